@@ -0,0 +1,47 @@
+//! Generates `asupersync.h` from the exported C ABI.
+//!
+//! The header is written to `$OUT_DIR/include/asupersync.h` on every build,
+//! and additionally copied to `ASUPERSYNC_FFI_HEADER_OUT` when that variable
+//! is set, so packaging scripts can pick it up without knowing `OUT_DIR`.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR"));
+    let include_dir = out_dir.join("include");
+    fs::create_dir_all(&include_dir).expect("create include dir");
+    let header = include_dir.join("asupersync.h");
+
+    let config =
+        cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("parse cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("generate asupersync.h")
+        .write_to_file(&header);
+
+    if let Some(dest) = env::var_os("ASUPERSYNC_FFI_HEADER_OUT") {
+        fs::copy(&header, &dest).expect("copy asupersync.h to ASUPERSYNC_FFI_HEADER_OUT");
+    }
+
+    // Consumed by tests/c_harness.rs to compile the C harness.
+    println!(
+        "cargo:rustc-env=ASUPERSYNC_FFI_INCLUDE_DIR={}",
+        include_dir.display()
+    );
+    println!(
+        "cargo:rustc-env=ASUPERSYNC_FFI_TARGET={}",
+        env::var("TARGET").expect("TARGET")
+    );
+    println!(
+        "cargo:rustc-env=ASUPERSYNC_FFI_HOST={}",
+        env::var("HOST").expect("HOST")
+    );
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-env-changed=ASUPERSYNC_FFI_HEADER_OUT");
+}
@@ -0,0 +1,539 @@
+//! The exported `asupersync_*` C ABI.
+//!
+//! Every entry point runs its body under [`boundary`], which catches panics
+//! and maps them to [`AsupersyncStatus::Panic`] so no unwind ever crosses into
+//! the host. Handles are validated against the registry on every call;
+//! nothing the host passes is dereferenced, and context and `user_data`
+//! pointers are only handed back to the host's own callbacks.
+
+// The only unsafe-code construct in this file is the `#[unsafe(no_mangle)]`
+// export attribute on each `extern "C"` entry point; there are no unsafe
+// blocks, functions or impls.
+#![allow(unsafe_code)]
+
+use crate::log::{self, AsupersyncLogFn, AsupersyncLogLevel, LogIds};
+use crate::region::RegionShared;
+use crate::registry::{RuntimeEntry, registry};
+use crate::status::{AsupersyncHandleResult, AsupersyncStatus};
+use crate::task::{AsupersyncDropFn, AsupersyncPollFn, HostTask, TaskShared};
+use asupersync::runtime::RuntimeBuilder;
+use std::any::Any;
+use std::ffi::{c_char, c_void};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Version of the C ABI described by `asupersync.h`.
+pub const ASUPERSYNC_ABI_VERSION: u32 = 1;
+
+/// Timeout value that waits without bound.
+pub const ASUPERSYNC_WAIT_FOREVER: u64 = u64::MAX;
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+/// Runs an entry point body, containing any panic.
+fn boundary<T>(entry: &'static str, on_panic: T, body: impl FnOnce() -> T) -> T {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(payload) => {
+            log::emit(
+                AsupersyncLogLevel::Error,
+                c"asupersync::ffi",
+                LogIds::default(),
+                format_args!("panic contained in {entry}: {}", panic_message(&*payload)),
+            );
+            on_panic
+        }
+    }
+}
+
+/// Logs a rejected handle before handing the status back to the host.
+fn checked<T>(
+    entry: &'static str,
+    ids: LogIds,
+    result: Result<T, AsupersyncStatus>,
+) -> Result<T, AsupersyncStatus> {
+    if let Err(status) = result {
+        log::emit(
+            AsupersyncLogLevel::Warn,
+            c"asupersync::ffi",
+            ids,
+            format_args!("{entry} rejected: {}", status.as_str()),
+        );
+    }
+    result
+}
+
+fn timeout_from_ms(timeout_ms: u64) -> Option<Duration> {
+    (timeout_ms != ASUPERSYNC_WAIT_FOREVER).then(|| Duration::from_millis(timeout_ms))
+}
+
+fn region_ids(region: u64) -> LogIds {
+    LogIds {
+        region,
+        ..LogIds::default()
+    }
+}
+
+fn task_ids(task: u64) -> LogIds {
+    LogIds {
+        task,
+        ..LogIds::default()
+    }
+}
+
+fn lookup_region(entry: &'static str, region: u64) -> Result<Arc<RegionShared>, AsupersyncStatus> {
+    let found = registry().regions.get(region).map(Arc::clone);
+    checked(entry, region_ids(region), found)
+}
+
+fn lookup_task(entry: &'static str, task: u64) -> Result<Arc<TaskShared>, AsupersyncStatus> {
+    let found = registry().tasks.get(task).map(Arc::clone);
+    checked(entry, task_ids(task), found)
+}
+
+// =============================================================================
+// Version and diagnostics
+// =============================================================================
+
+/// Returns [`ASUPERSYNC_ABI_VERSION`] as compiled into the library, so hosts
+/// can reject a mismatched `asupersync.h`.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_abi_version() -> u32 {
+    boundary("asupersync_abi_version", 0, || ASUPERSYNC_ABI_VERSION)
+}
+
+/// Returns a static, NUL-terminated name for a status code, or `"unknown"`.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_status_name(status: i32) -> *const c_char {
+    boundary("asupersync_status_name", c"unknown".as_ptr(), || {
+        AsupersyncStatus::from_raw(status)
+            .map_or(c"unknown", AsupersyncStatus::name)
+            .as_ptr()
+    })
+}
+
+/// Panics inside the boundary on purpose and returns the resulting status.
+///
+/// Lets embedders verify, from their own test suite, that a Rust panic
+/// surfaces as `ASUPERSYNC_STATUS_PANIC` plus an error log record rather
+/// than aborting the host.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_diagnostic_panic() -> AsupersyncStatus {
+    boundary(
+        "asupersync_diagnostic_panic",
+        AsupersyncStatus::Panic,
+        || -> AsupersyncStatus { panic!("asupersync_diagnostic_panic invoked") },
+    )
+}
+
+// =============================================================================
+// Logging
+// =============================================================================
+
+/// Registers the host log callback, replacing any previous one.
+///
+/// Records below `min_level` are not delivered. Passing a null `callback`
+/// stops delivery. `user_data` is passed back verbatim on every call.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_log_set_callback(
+    callback: Option<AsupersyncLogFn>,
+    user_data: *mut c_void,
+    min_level: i32,
+) -> AsupersyncStatus {
+    boundary(
+        "asupersync_log_set_callback",
+        AsupersyncStatus::Panic,
+        || {
+            let Some(min_level) = AsupersyncLogLevel::from_raw(min_level) else {
+                return AsupersyncStatus::InvalidArgument;
+            };
+            log::set_sink(callback, user_data, min_level);
+            AsupersyncStatus::Ok
+        },
+    )
+}
+
+// =============================================================================
+// Runtime
+// =============================================================================
+
+/// Creates a runtime. `worker_threads == 0` selects the default.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_runtime_create(worker_threads: u32) -> AsupersyncHandleResult {
+    boundary(
+        "asupersync_runtime_create",
+        AsupersyncHandleResult::err(AsupersyncStatus::Panic),
+        || {
+            let mut builder = RuntimeBuilder::new();
+            if worker_threads > 0 {
+                builder = builder.worker_threads(worker_threads as usize);
+            }
+            let runtime = match builder.build() {
+                Ok(runtime) => runtime,
+                Err(err) => {
+                    log::emit(
+                        AsupersyncLogLevel::Error,
+                        c"asupersync::ffi::runtime",
+                        LogIds::default(),
+                        format_args!("runtime build failed: {err}"),
+                    );
+                    return AsupersyncHandleResult::err(AsupersyncStatus::RuntimeError);
+                }
+            };
+            let inserted = registry().runtimes.insert(RuntimeEntry {
+                runtime,
+                open_regions: 0,
+            });
+            let Some(handle) = inserted else {
+                return AsupersyncHandleResult::err(AsupersyncStatus::RuntimeError);
+            };
+            log::emit(
+                AsupersyncLogLevel::Info,
+                c"asupersync::ffi::runtime",
+                LogIds {
+                    runtime: handle,
+                    ..LogIds::default()
+                },
+                format_args!("runtime created"),
+            );
+            AsupersyncHandleResult::ok(handle)
+        },
+    )
+}
+
+/// Destroys a runtime and joins its workers.
+///
+/// Fails with `ASUPERSYNC_STATUS_BUSY` while the runtime has open regions.
+/// Must not be called from a task callback.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_runtime_destroy(runtime: u64) -> AsupersyncStatus {
+    boundary(
+        "asupersync_runtime_destroy",
+        AsupersyncStatus::Panic,
+        || {
+            let ids = LogIds {
+                runtime,
+                ..LogIds::default()
+            };
+            let removed = {
+                let mut registry = registry();
+                let busy = registry
+                    .runtimes
+                    .get(runtime)
+                    .map(|entry| entry.open_regions > 0);
+                match busy {
+                    Ok(true) => Err(AsupersyncStatus::Busy),
+                    Ok(false) => registry.runtimes.remove(runtime),
+                    Err(status) => Err(status),
+                }
+            };
+            // The removed runtime shuts down here, outside the registry lock.
+            if let Err(status) = checked("asupersync_runtime_destroy", ids, removed) {
+                return status;
+            }
+            log::emit(
+                AsupersyncLogLevel::Info,
+                c"asupersync::ffi::runtime",
+                ids,
+                format_args!("runtime destroyed"),
+            );
+            AsupersyncStatus::Ok
+        },
+    )
+}
+
+// =============================================================================
+// Regions
+// =============================================================================
+
+/// Opens a region on `runtime`.
+#[unsafe(no_mangle)]
+#[allow(clippy::significant_drop_tightening)]
+pub extern "C" fn asupersync_region_open(runtime: u64) -> AsupersyncHandleResult {
+    boundary(
+        "asupersync_region_open",
+        AsupersyncHandleResult::err(AsupersyncStatus::Panic),
+        || {
+            let opened = {
+                let mut guard = registry();
+                let registry = &mut *guard;
+                registry.runtimes.get_mut(runtime).and_then(|entry| {
+                    let region = RegionShared::new(runtime, entry.runtime.handle());
+                    let handle = registry
+                        .regions
+                        .insert(Arc::new(region))
+                        .ok_or(AsupersyncStatus::RuntimeError)?;
+                    entry.open_regions += 1;
+                    Ok(handle)
+                })
+            };
+            let ids = LogIds {
+                runtime,
+                ..LogIds::default()
+            };
+            let result = checked("asupersync_region_open", ids, opened);
+            if let Ok(region) = result {
+                log::emit(
+                    AsupersyncLogLevel::Info,
+                    c"asupersync::ffi::region",
+                    LogIds { region, ..ids },
+                    format_args!("region opened"),
+                );
+            }
+            result.into()
+        },
+    )
+}
+
+/// Requests cancellation of every task in the region, including tasks
+/// spawned into it later. The region stays open.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_region_cancel(region: u64) -> AsupersyncStatus {
+    boundary("asupersync_region_cancel", AsupersyncStatus::Panic, || {
+        let shared = match lookup_region("asupersync_region_cancel", region) {
+            Ok(shared) => shared,
+            Err(status) => return status,
+        };
+        shared.cancel();
+        log::emit(
+            AsupersyncLogLevel::Info,
+            c"asupersync::ffi::region",
+            region_ids(region),
+            format_args!("region cancel requested"),
+        );
+        AsupersyncStatus::Ok
+    })
+}
+
+/// Blocks until the region has no live tasks.
+///
+/// Returns `ASUPERSYNC_STATUS_TIMEOUT` if tasks are still live after
+/// `timeout_ms` milliseconds; pass `ASUPERSYNC_WAIT_FOREVER` to wait without
+/// bound. Must not be called from a task callback.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_region_wait(region: u64, timeout_ms: u64) -> AsupersyncStatus {
+    boundary("asupersync_region_wait", AsupersyncStatus::Panic, || {
+        let shared = match lookup_region("asupersync_region_wait", region) {
+            Ok(shared) => shared,
+            Err(status) => return status,
+        };
+        if shared.wait_quiescent(timeout_from_ms(timeout_ms)) {
+            AsupersyncStatus::Ok
+        } else {
+            AsupersyncStatus::Timeout
+        }
+    })
+}
+
+/// Closes a region: stops admitting tasks, cancels the live ones and waits
+/// up to `timeout_ms` for them to finish, then releases the handle.
+///
+/// On `ASUPERSYNC_STATUS_TIMEOUT` the region stays valid (and closing), so
+/// the host can wait again or retry the close.
+#[unsafe(no_mangle)]
+#[allow(clippy::significant_drop_tightening)]
+pub extern "C" fn asupersync_region_close(region: u64, timeout_ms: u64) -> AsupersyncStatus {
+    boundary("asupersync_region_close", AsupersyncStatus::Panic, || {
+        let shared = match lookup_region("asupersync_region_close", region) {
+            Ok(shared) => shared,
+            Err(status) => return status,
+        };
+        shared.begin_close();
+        let ids = LogIds {
+            runtime: shared.runtime,
+            region,
+            task: 0,
+        };
+        if !shared.wait_quiescent(timeout_from_ms(timeout_ms)) {
+            log::emit(
+                AsupersyncLogLevel::Warn,
+                c"asupersync::ffi::region",
+                ids,
+                format_args!(
+                    "region close timed out with {} live task(s)",
+                    shared.live_tasks()
+                ),
+            );
+            return AsupersyncStatus::Timeout;
+        }
+
+        let removed = {
+            let mut registry = registry();
+            let removed = registry.regions.remove(region).map(drop);
+            if removed.is_ok()
+                && let Ok(entry) = registry.runtimes.get_mut(shared.runtime)
+            {
+                entry.open_regions = entry.open_regions.saturating_sub(1);
+            }
+            removed
+        };
+        // A concurrent close may have released the handle first.
+        if let Err(status) = checked("asupersync_region_close", ids, removed) {
+            return status;
+        }
+        log::emit(
+            AsupersyncLogLevel::Info,
+            c"asupersync::ffi::region",
+            ids,
+            format_args!("region closed"),
+        );
+        AsupersyncStatus::Ok
+    })
+}
+
+// =============================================================================
+// Tasks
+// =============================================================================
+
+/// Spawns a host task into `region`.
+///
+/// `poll` is called on a runtime worker thread with `context` and the new
+/// task's handle; see `ASUPERSYNC_POLL_READY` and `ASUPERSYNC_POLL_PENDING`.
+/// `drop_context`, if non-null, is called exactly once with `context` after
+/// the task finishes, or before this call returns if the task could not be
+/// spawned. `context` must be usable from any thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_task_spawn(
+    region: u64,
+    poll: Option<AsupersyncPollFn>,
+    context: *mut c_void,
+    drop_context: Option<AsupersyncDropFn>,
+) -> AsupersyncHandleResult {
+    boundary(
+        "asupersync_task_spawn",
+        AsupersyncHandleResult::err(AsupersyncStatus::Panic),
+        || {
+            let release_context = || {
+                if let Some(drop_context) = drop_context {
+                    drop_context(context);
+                }
+            };
+            let Some(poll) = poll else {
+                release_context();
+                return AsupersyncHandleResult::err(AsupersyncStatus::InvalidArgument);
+            };
+            let shared_region = match lookup_region("asupersync_task_spawn", region) {
+                Ok(shared) => shared,
+                Err(status) => {
+                    release_context();
+                    return AsupersyncHandleResult::err(status);
+                }
+            };
+
+            let shared = Arc::new(TaskShared::default());
+            let Some(task) = registry().tasks.insert(Arc::clone(&shared)) else {
+                release_context();
+                return AsupersyncHandleResult::err(AsupersyncStatus::RuntimeError);
+            };
+            let ids = LogIds {
+                runtime: shared_region.runtime,
+                region,
+                task,
+            };
+            if !shared_region.admit(task, &shared) {
+                let _ = registry().tasks.remove(task);
+                release_context();
+                log::emit(
+                    AsupersyncLogLevel::Warn,
+                    c"asupersync::ffi::task",
+                    ids,
+                    format_args!("spawn rejected: region is closing"),
+                );
+                return AsupersyncHandleResult::err(AsupersyncStatus::RegionClosed);
+            }
+
+            // From here on the `HostTask` owns the cleanup: if the runtime
+            // refuses the spawn, dropping the unstarted task releases the
+            // context, the handle and the region slot.
+            let host_task = HostTask::new(
+                ids,
+                shared,
+                Arc::clone(&shared_region),
+                poll,
+                context,
+                drop_context,
+            );
+            if let Err(err) = shared_region
+                .spawner
+                .try_spawn_with_cx(move |cx| host_task.with_cx(cx))
+            {
+                log::emit(
+                    AsupersyncLogLevel::Error,
+                    c"asupersync::ffi::task",
+                    ids,
+                    format_args!("runtime refused spawn: {err:?}"),
+                );
+                return AsupersyncHandleResult::err(AsupersyncStatus::RuntimeError);
+            }
+            log::emit(
+                AsupersyncLogLevel::Debug,
+                c"asupersync::ffi::task",
+                ids,
+                format_args!("task spawned"),
+            );
+            AsupersyncHandleResult::ok(task)
+        },
+    )
+}
+
+/// Schedules another poll of a pending task. Safe to call from any thread,
+/// including from inside the task's own poll callback.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_task_wake(task: u64) -> AsupersyncStatus {
+    boundary(
+        "asupersync_task_wake",
+        AsupersyncStatus::Panic,
+        || match lookup_task("asupersync_task_wake", task) {
+            Ok(shared) => {
+                shared.wake();
+                AsupersyncStatus::Ok
+            }
+            Err(status) => status,
+        },
+    )
+}
+
+/// Requests cancellation of a single task and wakes it.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_task_cancel(task: u64) -> AsupersyncStatus {
+    boundary(
+        "asupersync_task_cancel",
+        AsupersyncStatus::Panic,
+        || match lookup_task("asupersync_task_cancel", task) {
+            Ok(shared) => {
+                shared.request_cancel();
+                log::emit(
+                    AsupersyncLogLevel::Debug,
+                    c"asupersync::ffi::task",
+                    task_ids(task),
+                    format_args!("task cancel requested"),
+                );
+                AsupersyncStatus::Ok
+            }
+            Err(status) => status,
+        },
+    )
+}
+
+/// Returns `ASUPERSYNC_STATUS_CANCELLED` once cancellation has been requested
+/// for the task (directly, through its region, or by runtime shutdown), and
+/// `ASUPERSYNC_STATUS_OK` otherwise.
+#[unsafe(no_mangle)]
+pub extern "C" fn asupersync_task_checkpoint(task: u64) -> AsupersyncStatus {
+    boundary(
+        "asupersync_task_checkpoint",
+        AsupersyncStatus::Panic,
+        || match lookup_task("asupersync_task_checkpoint", task) {
+            Ok(shared) if shared.is_cancel_requested() => AsupersyncStatus::Cancelled,
+            Ok(_) => AsupersyncStatus::Ok,
+            Err(status) => status,
+        },
+    )
+}
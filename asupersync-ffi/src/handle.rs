@@ -0,0 +1,205 @@
+//! Generation-tagged handle tables.
+//!
+//! The host never sees a Rust pointer. Every object it owns is named by a
+//! `u64` handle packing three fields:
+//!
+//! ```text
+//! bits 56..64  kind tag      (runtime, region, task)
+//! bits 32..56  generation    (24 bits, never zero)
+//! bits  0..32  slot index
+//! ```
+//!
+//! Releasing a slot bumps its generation, so a handle kept past release no
+//! longer matches and is rejected with
+//! [`AsupersyncStatus::StaleHandle`] instead of reaching freed memory. The
+//! zero handle is never issued and doubles as the C `NULL` sentinel.
+
+use crate::status::AsupersyncStatus;
+
+const INDEX_BITS: u32 = 32;
+const GENERATION_BITS: u32 = 24;
+const GENERATION_MASK: u32 = (1 << GENERATION_BITS) - 1;
+const KIND_SHIFT: u32 = INDEX_BITS + GENERATION_BITS;
+
+/// Kind of object a handle names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandleKind {
+    /// An `asupersync` runtime.
+    Runtime,
+    /// A region: a group of host tasks that close to quiescence together.
+    Region,
+    /// A host task driven by a C poll callback.
+    Task,
+}
+
+impl HandleKind {
+    const fn tag(self) -> u64 {
+        match self {
+            Self::Runtime => 0x01,
+            Self::Region => 0x02,
+            Self::Task => 0x03,
+        }
+    }
+
+    /// Returns the kind encoded in `handle`, if any.
+    #[must_use]
+    pub const fn of(handle: u64) -> Option<Self> {
+        match handle >> KIND_SHIFT {
+            0x01 => Some(Self::Runtime),
+            0x02 => Some(Self::Region),
+            0x03 => Some(Self::Task),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a handle into its slot index and generation.
+#[allow(clippy::cast_possible_truncation)] // both fields are masked to fit
+const fn decode(handle: u64) -> (u32, u32) {
+    (
+        handle as u32,
+        (handle >> INDEX_BITS) as u32 & GENERATION_MASK,
+    )
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Slot table issuing generation-tagged handles of a single kind.
+pub struct HandleTable<T> {
+    kind: HandleKind,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> HandleTable<T> {
+    pub const fn new(kind: HandleKind) -> Self {
+        Self {
+            kind,
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn encode(&self, index: u32, generation: u32) -> u64 {
+        (self.kind.tag() << KIND_SHIFT) | (u64::from(generation) << INDEX_BITS) | u64::from(index)
+    }
+
+    /// Stores `value` and returns its handle, or `None` if the table has
+    /// exhausted its index space.
+    pub fn insert(&mut self, value: T) -> Option<u64> {
+        let index = if let Some(index) = self.free.pop() {
+            index
+        } else {
+            let index = u32::try_from(self.slots.len()).ok()?;
+            self.slots.push(Slot {
+                generation: 1,
+                value: None,
+            });
+            index
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        let generation = slot.generation;
+        Some(self.encode(index, generation))
+    }
+
+    fn locate(&self, handle: u64) -> Result<u32, AsupersyncStatus> {
+        if HandleKind::of(handle) != Some(self.kind) {
+            return Err(AsupersyncStatus::InvalidHandle);
+        }
+        let (index, generation) = decode(handle);
+        let slot = self
+            .slots
+            .get(index as usize)
+            .ok_or(AsupersyncStatus::InvalidHandle)?;
+        if generation == 0 || generation > slot.generation {
+            // Generations only grow, so a future one was never issued.
+            return Err(AsupersyncStatus::InvalidHandle);
+        }
+        if generation != slot.generation || slot.value.is_none() {
+            return Err(AsupersyncStatus::StaleHandle);
+        }
+        Ok(index)
+    }
+
+    /// Returns the object named by `handle`.
+    pub fn get(&self, handle: u64) -> Result<&T, AsupersyncStatus> {
+        let index = self.locate(handle)?;
+        self.slots[index as usize]
+            .value
+            .as_ref()
+            .ok_or(AsupersyncStatus::StaleHandle)
+    }
+
+    /// Returns the object named by `handle` mutably.
+    pub fn get_mut(&mut self, handle: u64) -> Result<&mut T, AsupersyncStatus> {
+        let index = self.locate(handle)?;
+        self.slots[index as usize]
+            .value
+            .as_mut()
+            .ok_or(AsupersyncStatus::StaleHandle)
+    }
+
+    /// Releases `handle`, invalidating every copy of it.
+    pub fn remove(&mut self, handle: u64) -> Result<T, AsupersyncStatus> {
+        let index = self.locate(handle)?;
+        let slot = &mut self.slots[index as usize];
+        let value = slot.value.take().ok_or(AsupersyncStatus::StaleHandle)?;
+        // Retire the slot for good once its generation space is exhausted
+        // rather than wrapping and resurrecting old handles.
+        if slot.generation < GENERATION_MASK {
+            slot.generation += 1;
+            self.free.push(index);
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_and_foreign_handles_are_invalid() {
+        let mut table = HandleTable::new(HandleKind::Region);
+        let handle = table.insert("region").expect("insert");
+        assert_eq!(table.get(0), Err(AsupersyncStatus::InvalidHandle));
+
+        let mut runtimes = HandleTable::new(HandleKind::Runtime);
+        let runtime = runtimes.insert(()).expect("insert");
+        assert_eq!(table.get(runtime), Err(AsupersyncStatus::InvalidHandle));
+        assert_eq!(runtimes.get(handle), Err(AsupersyncStatus::InvalidHandle));
+        assert_eq!(HandleKind::of(handle), Some(HandleKind::Region));
+    }
+
+    #[test]
+    fn released_handles_are_stale_and_slots_are_reused() {
+        let mut table = HandleTable::new(HandleKind::Task);
+        let first = table.insert(1).expect("insert");
+        assert_eq!(table.remove(first), Ok(1));
+        assert_eq!(table.remove(first), Err(AsupersyncStatus::StaleHandle));
+        assert_eq!(table.get(first), Err(AsupersyncStatus::StaleHandle));
+
+        let second = table.insert(2).expect("insert");
+        assert_ne!(first, second, "reused slot must carry a new generation");
+        assert_eq!(first & u64::from(u32::MAX), second & u64::from(u32::MAX));
+        assert_eq!(table.get(first), Err(AsupersyncStatus::StaleHandle));
+        assert_eq!(table.get(second), Ok(&2));
+    }
+
+    #[test]
+    fn forged_generations_are_invalid() {
+        let mut table = HandleTable::new(HandleKind::Task);
+        let handle = table.insert(()).expect("insert");
+        let future = handle + (1 << INDEX_BITS);
+        assert_eq!(table.get(future), Err(AsupersyncStatus::InvalidHandle));
+        let out_of_range = handle + 7;
+        assert_eq!(
+            table.get(out_of_range),
+            Err(AsupersyncStatus::InvalidHandle)
+        );
+    }
+}
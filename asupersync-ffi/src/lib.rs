@@ -0,0 +1,63 @@
+//! Stable C ABI for embedding the Asupersync runtime in non-Rust hosts.
+//!
+//! `asupersync-ffi` is the workspace's native-host boundary crate, the C
+//! counterpart of `asupersync-browser-core`. It builds as a `cdylib` and a
+//! `staticlib` and generates `asupersync.h` (under `$OUT_DIR/include`, or at
+//! `ASUPERSYNC_FFI_HEADER_OUT` when set) from the exported functions.
+//!
+//! # Handles
+//!
+//! Runtimes, regions and tasks are named by opaque `uint64_t` handles that
+//! carry a kind tag and a generation. The library validates every handle it
+//! is given: a zero, forged or wrong-kind handle yields
+//! `ASUPERSYNC_STATUS_INVALID_HANDLE`, and a released one (double close,
+//! use-after-free) yields `ASUPERSYNC_STATUS_STALE_HANDLE`. Neither is
+//! undefined behavior.
+//!
+//! # Structure
+//!
+//! A region groups host tasks. `asupersync_region_close` stops admission,
+//! cancels the live tasks and waits, up to a timeout, for them to finish, so
+//! a region never closes with work still running. A runtime refuses to be
+//! destroyed while it has open regions.
+//!
+//! # Tasks
+//!
+//! A host task is a C poll callback. It returns `ASUPERSYNC_POLL_PENDING`
+//! after arranging for `asupersync_task_wake`, or `ASUPERSYNC_POLL_READY`
+//! when done. Cancellation is cooperative: the callback observes it through
+//! `asupersync_task_checkpoint`.
+//!
+//! # Threading and panics
+//!
+//! Every entry point may be called from any thread. Blocking calls
+//! (`asupersync_region_wait`, `asupersync_region_close`,
+//! `asupersync_runtime_destroy`) must not be made from inside a task
+//! callback. Rust panics never unwind into the host: they are caught at the
+//! boundary, reported as `ASUPERSYNC_STATUS_PANIC` and logged at error level
+//! through the callback installed with `asupersync_log_set_callback`.
+//!
+#![deny(unsafe_code)]
+
+mod exports;
+mod handle;
+mod log;
+mod region;
+mod registry;
+mod status;
+mod task;
+
+pub use exports::{
+    ASUPERSYNC_ABI_VERSION, ASUPERSYNC_WAIT_FOREVER, asupersync_abi_version,
+    asupersync_diagnostic_panic, asupersync_log_set_callback, asupersync_region_cancel,
+    asupersync_region_close, asupersync_region_open, asupersync_region_wait,
+    asupersync_runtime_create, asupersync_runtime_destroy, asupersync_status_name,
+    asupersync_task_cancel, asupersync_task_checkpoint, asupersync_task_spawn,
+    asupersync_task_wake,
+};
+pub use handle::HandleKind;
+pub use log::{AsupersyncLogFn, AsupersyncLogLevel, AsupersyncLogRecord};
+pub use status::{AsupersyncHandleResult, AsupersyncStatus};
+pub use task::{
+    ASUPERSYNC_POLL_PENDING, ASUPERSYNC_POLL_READY, AsupersyncDropFn, AsupersyncPollFn,
+};
@@ -0,0 +1,126 @@
+//! Structured log delivery into the host's logging.
+//!
+//! The host registers one callback with `asupersync_log_set_callback`. Every
+//! lifecycle event at the FFI boundary (runtime, region and task transitions,
+//! rejected handles, contained panics) is delivered as an
+//! [`AsupersyncLogRecord`] carrying the handles it concerns, so hosts can
+//! attach them as structured fields instead of parsing messages.
+
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+/// Severity of a log record.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AsupersyncLogLevel {
+    /// Per-poll detail.
+    Trace = 0,
+    /// Lifecycle detail useful while debugging an embedding.
+    Debug = 1,
+    /// Normal lifecycle transitions.
+    Info = 2,
+    /// Recoverable misuse, such as a stale handle from the host.
+    Warn = 3,
+    /// Failures, including panics contained at the boundary.
+    Error = 4,
+}
+
+impl AsupersyncLogLevel {
+    /// Decodes a level passed in from C, rejecting out-of-range values.
+    #[must_use]
+    pub const fn from_raw(raw: i32) -> Option<Self> {
+        match raw {
+            0 => Some(Self::Trace),
+            1 => Some(Self::Debug),
+            2 => Some(Self::Info),
+            3 => Some(Self::Warn),
+            4 => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// One log event, valid only for the duration of the callback.
+///
+/// `target` and `message` are NUL-terminated UTF-8. Handle fields are zero
+/// when the event does not concern an object of that kind.
+#[repr(C)]
+#[derive(Debug)]
+pub struct AsupersyncLogRecord {
+    /// Severity.
+    pub level: AsupersyncLogLevel,
+    /// Subsystem that produced the event, e.g. `asupersync::ffi::region`.
+    pub target: *const c_char,
+    /// Human-readable message.
+    pub message: *const c_char,
+    /// Runtime the event concerns, or zero.
+    pub runtime: u64,
+    /// Region the event concerns, or zero.
+    pub region: u64,
+    /// Task the event concerns, or zero.
+    pub task: u64,
+}
+
+/// Host log callback. May be invoked concurrently from runtime worker threads.
+pub type AsupersyncLogFn =
+    extern "C" fn(record: *const AsupersyncLogRecord, user_data: *mut c_void);
+
+#[derive(Clone, Copy)]
+struct LogSink {
+    callback: AsupersyncLogFn,
+    /// Host `user_data`, kept as an exposed address so the sink is `Send`.
+    user_data: usize,
+    min_level: AsupersyncLogLevel,
+}
+
+static SINK: RwLock<Option<LogSink>> = RwLock::new(None);
+
+/// Handles a log record concerns.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogIds {
+    pub runtime: u64,
+    pub region: u64,
+    pub task: u64,
+}
+
+/// Installs (or, with `None`, removes) the host log callback.
+pub fn set_sink(
+    callback: Option<AsupersyncLogFn>,
+    user_data: *mut c_void,
+    min_level: AsupersyncLogLevel,
+) {
+    let sink = callback.map(|callback| LogSink {
+        callback,
+        user_data: user_data.expose_provenance(),
+        min_level,
+    });
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+}
+
+/// Delivers a record to the host callback, if one accepts `level`.
+///
+/// Callers must not hold the handle registry lock: the host is free to call
+/// back into the ABI from its callback.
+pub fn emit(level: AsupersyncLogLevel, target: &CStr, ids: LogIds, message: fmt::Arguments<'_>) {
+    // Copy the sink out so the callback runs without the lock held.
+    let Some(sink) = *SINK.read().unwrap_or_else(PoisonError::into_inner) else {
+        return;
+    };
+    if level < sink.min_level {
+        return;
+    }
+    let message = CString::new(message.to_string().replace('\0', "\u{FFFD}")).unwrap_or_default();
+    let record = AsupersyncLogRecord {
+        level,
+        target: target.as_ptr(),
+        message: message.as_ptr(),
+        runtime: ids.runtime,
+        region: ids.region,
+        task: ids.task,
+    };
+    (sink.callback)(
+        &raw const record,
+        std::ptr::with_exposed_provenance_mut(sink.user_data),
+    );
+}
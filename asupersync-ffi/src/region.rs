@@ -0,0 +1,118 @@
+//! Host regions: task groups that cancel and close to quiescence together.
+//!
+//! An FFI region owns every task the host spawns into it. Closing a region
+//! stops admission, requests cancellation of its live tasks and waits for
+//! them to finish, mirroring how an `asupersync` region drains before it
+//! closes. The tasks themselves run in the runtime's root region; the FFI
+//! region is the structure the host observes and waits on.
+
+use crate::task::TaskShared;
+use asupersync::runtime::RuntimeHandle;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+struct RegionState {
+    /// Live tasks keyed by handle.
+    tasks: BTreeMap<u64, Arc<TaskShared>>,
+    closing: bool,
+    cancel_requested: bool,
+}
+
+/// State shared between a region handle and the tasks running in it.
+pub struct RegionShared {
+    /// Runtime the region was opened on.
+    pub runtime: u64,
+    pub spawner: RuntimeHandle,
+    state: Mutex<RegionState>,
+    quiescent: Condvar,
+}
+
+impl RegionShared {
+    pub fn new(runtime: u64, spawner: RuntimeHandle) -> Self {
+        Self {
+            runtime,
+            spawner,
+            state: Mutex::new(RegionState::default()),
+            quiescent: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RegionState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a new task, returning `false` if the region is closing. A
+    /// task admitted into a cancelled region starts out cancelled.
+    pub fn admit(&self, handle: u64, task: &Arc<TaskShared>) -> bool {
+        let mut state = self.lock();
+        if state.closing {
+            return false;
+        }
+        if state.cancel_requested {
+            task.mark_cancelled();
+        }
+        state.tasks.insert(handle, Arc::clone(task));
+        true
+    }
+
+    /// Removes a finished task, waking waiters once the region is quiescent.
+    pub fn finish(&self, handle: u64) {
+        let mut state = self.lock();
+        if state.tasks.remove(&handle).is_some() && state.tasks.is_empty() {
+            self.quiescent.notify_all();
+        }
+    }
+
+    /// Requests cancellation of every live and future task.
+    pub fn cancel(&self) {
+        let tasks: Vec<_> = {
+            let mut state = self.lock();
+            state.cancel_requested = true;
+            state.tasks.values().cloned().collect()
+        };
+        // Wake outside the lock: a woken task may finish on another thread
+        // and call back into `finish`.
+        for task in tasks {
+            task.request_cancel();
+        }
+    }
+
+    /// Stops admission and cancels the live tasks.
+    pub fn begin_close(&self) {
+        self.lock().closing = true;
+        self.cancel();
+    }
+
+    /// Number of live tasks.
+    pub fn live_tasks(&self) -> usize {
+        self.lock().tasks.len()
+    }
+
+    /// Blocks until the region has no live tasks, or until `timeout`
+    /// elapses. Returns whether the region is quiescent.
+    #[allow(clippy::significant_drop_tightening)]
+    pub fn wait_quiescent(&self, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.lock();
+        while !state.tasks.is_empty() {
+            state = match deadline {
+                None => self
+                    .quiescent
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        return false;
+                    };
+                    self.quiescent
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+        true
+    }
+}
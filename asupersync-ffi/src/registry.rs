@@ -0,0 +1,36 @@
+//! Process-wide table of every object the host holds a handle to.
+
+use crate::handle::{HandleKind, HandleTable};
+use crate::region::RegionShared;
+use crate::task::TaskShared;
+use asupersync::runtime::Runtime;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A runtime owned by the host.
+pub struct RuntimeEntry {
+    pub runtime: Runtime,
+    /// Regions opened on this runtime and not yet closed.
+    pub open_regions: usize,
+}
+
+/// Handle tables for every object kind.
+///
+/// The lock is held only for table lookups and updates: never while calling
+/// into the host, waiting, or dropping a runtime.
+pub struct Registry {
+    pub runtimes: HandleTable<RuntimeEntry>,
+    pub regions: HandleTable<Arc<RegionShared>>,
+    pub tasks: HandleTable<Arc<TaskShared>>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    runtimes: HandleTable::new(HandleKind::Runtime),
+    regions: HandleTable::new(HandleKind::Region),
+    tasks: HandleTable::new(HandleKind::Task),
+});
+
+/// Locks the registry. A panic contained at the boundary cannot leave the
+/// tables half-updated, so a poisoned lock is still safe to use.
+pub fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
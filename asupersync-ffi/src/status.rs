@@ -0,0 +1,124 @@
+//! Status codes returned across the C ABI.
+
+use std::ffi::CStr;
+
+/// Result code returned by every `asupersync_*` entry point.
+///
+/// `ASUPERSYNC_STATUS_OK` is zero; every failure is a distinct positive code so
+/// hosts can test `status != ASUPERSYNC_STATUS_OK` without decoding it first.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsupersyncStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// The handle was never issued, is zero, or names the wrong kind of
+    /// object (for example a region handle passed where a runtime is
+    /// expected).
+    InvalidHandle = 1,
+    /// The handle was issued but its object has since been released
+    /// (use-after-free or double-free from the host).
+    StaleHandle = 2,
+    /// An argument other than a handle was rejected.
+    InvalidArgument = 3,
+    /// A bounded wait elapsed before its condition held.
+    Timeout = 4,
+    /// The region is closing and no longer admits tasks.
+    RegionClosed = 5,
+    /// The object still owns live children (for example a runtime with open
+    /// regions) and cannot be released yet.
+    Busy = 6,
+    /// Cancellation has been requested for the task.
+    Cancelled = 7,
+    /// The runtime rejected the operation.
+    RuntimeError = 8,
+    /// A Rust panic was caught at the FFI boundary.
+    Panic = 9,
+}
+
+impl AsupersyncStatus {
+    /// Decodes a status passed in from C, rejecting unknown codes.
+    #[must_use]
+    pub const fn from_raw(raw: i32) -> Option<Self> {
+        match raw {
+            0 => Some(Self::Ok),
+            1 => Some(Self::InvalidHandle),
+            2 => Some(Self::StaleHandle),
+            3 => Some(Self::InvalidArgument),
+            4 => Some(Self::Timeout),
+            5 => Some(Self::RegionClosed),
+            6 => Some(Self::Busy),
+            7 => Some(Self::Cancelled),
+            8 => Some(Self::RuntimeError),
+            9 => Some(Self::Panic),
+            _ => None,
+        }
+    }
+
+    /// Returns a static, human-readable name for the status.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::InvalidHandle => "invalid_handle",
+            Self::StaleHandle => "stale_handle",
+            Self::InvalidArgument => "invalid_argument",
+            Self::Timeout => "timeout",
+            Self::RegionClosed => "region_closed",
+            Self::Busy => "busy",
+            Self::Cancelled => "cancelled",
+            Self::RuntimeError => "runtime_error",
+            Self::Panic => "panic",
+        }
+    }
+
+    /// Returns [`Self::as_str`] as a NUL-terminated string for C callers.
+    #[must_use]
+    pub const fn name(self) -> &'static CStr {
+        match self {
+            Self::Ok => c"ok",
+            Self::InvalidHandle => c"invalid_handle",
+            Self::StaleHandle => c"stale_handle",
+            Self::InvalidArgument => c"invalid_argument",
+            Self::Timeout => c"timeout",
+            Self::RegionClosed => c"region_closed",
+            Self::Busy => c"busy",
+            Self::Cancelled => c"cancelled",
+            Self::RuntimeError => c"runtime_error",
+            Self::Panic => c"panic",
+        }
+    }
+}
+
+/// A status paired with the handle it produced.
+///
+/// `handle` is zero unless `status` is `ASUPERSYNC_STATUS_OK`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsupersyncHandleResult {
+    /// Outcome of the call.
+    pub status: AsupersyncStatus,
+    /// The new handle on success, otherwise zero.
+    pub handle: u64,
+}
+
+impl AsupersyncHandleResult {
+    pub(crate) const fn ok(handle: u64) -> Self {
+        Self {
+            status: AsupersyncStatus::Ok,
+            handle,
+        }
+    }
+
+    pub(crate) const fn err(status: AsupersyncStatus) -> Self {
+        Self { status, handle: 0 }
+    }
+}
+
+impl From<Result<u64, AsupersyncStatus>> for AsupersyncHandleResult {
+    fn from(result: Result<u64, AsupersyncStatus>) -> Self {
+        match result {
+            Ok(handle) => Self::ok(handle),
+            Err(status) => Self::err(status),
+        }
+    }
+}
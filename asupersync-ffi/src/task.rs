@@ -0,0 +1,196 @@
+//! Host tasks: C poll callbacks driven by a shim future.
+//!
+//! The runtime polls a [`HostTask`] like any other future. Each poll calls
+//! the host's `poll` callback with its context pointer and task handle; the
+//! callback returns [`ASUPERSYNC_POLL_PENDING`] after arranging for
+//! `asupersync_task_wake` to be called, or [`ASUPERSYNC_POLL_READY`] when the
+//! task is done. Any other value ends the task as failed with that code.
+//!
+//! Cancellation is cooperative, as everywhere in `asupersync`: a cancelled
+//! task is woken and keeps being polled, and the callback observes the
+//! request through `asupersync_task_checkpoint` and winds down.
+
+use crate::log::{self, AsupersyncLogLevel, LogIds};
+use crate::region::RegionShared;
+use crate::registry::registry;
+use asupersync::cx::Cx;
+use std::ffi::c_void;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// Poll result: the task has finished.
+pub const ASUPERSYNC_POLL_READY: i32 = 0;
+
+/// Poll result: the task is waiting and will call `asupersync_task_wake`.
+pub const ASUPERSYNC_POLL_PENDING: i32 = 1;
+
+/// Host poll callback, called on a runtime worker thread.
+pub type AsupersyncPollFn = extern "C" fn(context: *mut c_void, task: u64) -> i32;
+
+/// Releases a task's context once the task has finished or failed to start.
+pub type AsupersyncDropFn = extern "C" fn(context: *mut c_void);
+
+/// Wake and cancellation state shared between a task handle and its future.
+#[derive(Default)]
+pub struct TaskShared {
+    cancel_requested: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl TaskShared {
+    fn register(&self, waker: &Waker) {
+        let mut slot = self.waker.lock().unwrap_or_else(PoisonError::into_inner);
+        if !slot
+            .as_ref()
+            .is_some_and(|current| current.will_wake(waker))
+        {
+            *slot = Some(waker.clone());
+        }
+    }
+
+    /// Schedules another poll of the task.
+    pub fn wake(&self) {
+        let waker = self
+            .waker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub fn mark_cancelled(&self) {
+        self.cancel_requested.store(true, Ordering::Release);
+    }
+
+    /// Marks the task cancelled and wakes it so the callback can observe it.
+    pub fn request_cancel(&self) {
+        self.mark_cancelled();
+        self.wake();
+    }
+
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_requested.load(Ordering::Acquire)
+    }
+}
+
+/// The shim future wrapping a host poll callback.
+///
+/// Dropping a `HostTask` that never reported completion (for example because
+/// the runtime refused to spawn it) still releases its context, handle and
+/// region slot, so the host sees the same cleanup on every path.
+pub struct HostTask {
+    ids: LogIds,
+    shared: Arc<TaskShared>,
+    region: Arc<RegionShared>,
+    poll: AsupersyncPollFn,
+    /// Host context pointer, kept as an exposed address so the future is
+    /// `Send`. The host promises the context may be used from any thread.
+    context: usize,
+    drop_context: Option<AsupersyncDropFn>,
+    cx: Option<Cx>,
+    finished: bool,
+}
+
+impl HostTask {
+    pub fn new(
+        ids: LogIds,
+        shared: Arc<TaskShared>,
+        region: Arc<RegionShared>,
+        poll: AsupersyncPollFn,
+        context: *mut c_void,
+        drop_context: Option<AsupersyncDropFn>,
+    ) -> Self {
+        Self {
+            ids,
+            shared,
+            region,
+            poll,
+            context: context.expose_provenance(),
+            drop_context,
+            cx: None,
+            finished: false,
+        }
+    }
+
+    /// Attaches the runtime context the task runs under.
+    pub const fn with_cx(mut self, cx: Cx) -> Self {
+        self.cx = Some(cx);
+        self
+    }
+
+    const fn context_ptr(&self) -> *mut c_void {
+        std::ptr::with_exposed_provenance_mut(self.context)
+    }
+
+    /// Releases everything the task holds. Runs exactly once.
+    fn finish(&mut self, code: Option<i32>) {
+        if std::mem::replace(&mut self.finished, true) {
+            return;
+        }
+        if let Some(drop_context) = self.drop_context {
+            drop_context(self.context_ptr());
+        }
+        // Release the handle before reporting quiescence, so a host that
+        // returns from a region wait never observes a live task handle.
+        let _ = registry().tasks.remove(self.ids.task);
+        self.region.finish(self.ids.task);
+
+        match code {
+            Some(ASUPERSYNC_POLL_READY) => log::emit(
+                AsupersyncLogLevel::Debug,
+                c"asupersync::ffi::task",
+                self.ids,
+                format_args!("task completed"),
+            ),
+            Some(code) => log::emit(
+                AsupersyncLogLevel::Error,
+                c"asupersync::ffi::task",
+                self.ids,
+                format_args!("task failed with code {code}"),
+            ),
+            None => log::emit(
+                AsupersyncLogLevel::Warn,
+                c"asupersync::ffi::task",
+                self.ids,
+                format_args!("task dropped before completion"),
+            ),
+        }
+    }
+}
+
+impl Future for HostTask {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(());
+        }
+        // Runtime shutdown cancels the task's own context; surface that to
+        // the host the same way as an explicit cancel.
+        if this.cx.as_ref().is_some_and(Cx::is_cancel_requested) {
+            this.shared.mark_cancelled();
+        }
+        // Register before calling out, so a wake issued during the callback
+        // schedules another poll instead of being lost.
+        this.shared.register(cx.waker());
+        match (this.poll)(this.context_ptr(), this.ids.task) {
+            ASUPERSYNC_POLL_PENDING => Poll::Pending,
+            code => {
+                this.finish(Some(code));
+                Poll::Ready(())
+            }
+        }
+    }
+}
+
+impl Drop for HostTask {
+    fn drop(&mut self) {
+        self.finish(None);
+    }
+}
@@ -0,0 +1,239 @@
+/*
+ * C-side conformance harness for the asupersync C ABI.
+ *
+ * Built and run by tests/c_harness.rs against the freshly built library.
+ * Exits non-zero, naming the failed check, on the first mismatch.
+ */
+
+#include <stdatomic.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "asupersync.h"
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+                    __LINE__, #cond);                                      \
+            exit(1);                                                       \
+        }                                                                  \
+    } while (0)
+
+#define CHECK_STATUS(expr, expected)                                       \
+    do {                                                                   \
+        AsupersyncStatus status_ = (expr);                                 \
+        if (status_ != (expected)) {                                       \
+            fprintf(stderr, "%s:%d: %s returned %s, expected %s\n",        \
+                    __FILE__, __LINE__, #expr,                             \
+                    asupersync_status_name(status_),                       \
+                    asupersync_status_name(expected));                     \
+            exit(1);                                                       \
+        }                                                                  \
+    } while (0)
+
+/* ---- logging ----------------------------------------------------------- */
+
+struct log_counts {
+    atomic_int records;
+    atomic_int warnings;
+    atomic_int errors;
+    atomic_int panics;
+};
+
+static void on_log(const AsupersyncLogRecord *record, void *user_data) {
+    struct log_counts *counts = user_data;
+    atomic_fetch_add(&counts->records, 1);
+    if (record->level == ASUPERSYNC_LOG_LEVEL_WARN) {
+        atomic_fetch_add(&counts->warnings, 1);
+    }
+    if (record->level == ASUPERSYNC_LOG_LEVEL_ERROR) {
+        atomic_fetch_add(&counts->errors, 1);
+        if (strstr(record->message, "panic contained") != NULL) {
+            atomic_fetch_add(&counts->panics, 1);
+        }
+    }
+}
+
+/* ---- tasks -------------------------------------------------------------- */
+
+struct counter_task {
+    atomic_int polls;
+    int target;
+    atomic_int dropped;
+};
+
+/* Yields `target` times, waking itself each time, then completes. */
+static int32_t poll_counter(void *context, uint64_t task) {
+    struct counter_task *state = context;
+    int polls = atomic_fetch_add(&state->polls, 1) + 1;
+    if (polls >= state->target) {
+        return ASUPERSYNC_POLL_READY;
+    }
+    if (asupersync_task_wake(task) != ASUPERSYNC_STATUS_OK) {
+        return -1;
+    }
+    return ASUPERSYNC_POLL_PENDING;
+}
+
+/* Parks until cancelled, then completes. */
+static int32_t poll_until_cancelled(void *context, uint64_t task) {
+    struct counter_task *state = context;
+    atomic_fetch_add(&state->polls, 1);
+    if (asupersync_task_checkpoint(task) == ASUPERSYNC_STATUS_CANCELLED) {
+        return ASUPERSYNC_POLL_READY;
+    }
+    return ASUPERSYNC_POLL_PENDING;
+}
+
+static void drop_counter(void *context) {
+    struct counter_task *state = context;
+    atomic_fetch_add(&state->dropped, 1);
+}
+
+/* ---- scenarios ---------------------------------------------------------- */
+
+static void lifecycle(uint64_t runtime) {
+    AsupersyncHandleResult region = asupersync_region_open(runtime);
+    CHECK_STATUS(region.status, ASUPERSYNC_STATUS_OK);
+
+    enum { TASKS = 8 };
+    struct counter_task tasks[TASKS];
+    for (int i = 0; i < TASKS; i++) {
+        atomic_init(&tasks[i].polls, 0);
+        atomic_init(&tasks[i].dropped, 0);
+        tasks[i].target = 3 + i;
+        AsupersyncHandleResult task = asupersync_task_spawn(
+            region.handle, poll_counter, &tasks[i], drop_counter);
+        CHECK_STATUS(task.status, ASUPERSYNC_STATUS_OK);
+        CHECK(task.handle != 0);
+    }
+
+    CHECK_STATUS(asupersync_region_wait(region.handle, 10000),
+                 ASUPERSYNC_STATUS_OK);
+    for (int i = 0; i < TASKS; i++) {
+        CHECK(atomic_load(&tasks[i].polls) == tasks[i].target);
+        CHECK(atomic_load(&tasks[i].dropped) == 1);
+    }
+
+    /* The runtime cannot be destroyed under an open region. */
+    CHECK_STATUS(asupersync_runtime_destroy(runtime), ASUPERSYNC_STATUS_BUSY);
+    CHECK_STATUS(asupersync_region_close(region.handle, 10000),
+                 ASUPERSYNC_STATUS_OK);
+}
+
+static void cancellation_and_timeout(uint64_t runtime) {
+    AsupersyncHandleResult region = asupersync_region_open(runtime);
+    CHECK_STATUS(region.status, ASUPERSYNC_STATUS_OK);
+
+    struct counter_task parked;
+    atomic_init(&parked.polls, 0);
+    atomic_init(&parked.dropped, 0);
+    parked.target = 0;
+    AsupersyncHandleResult task = asupersync_task_spawn(
+        region.handle, poll_until_cancelled, &parked, drop_counter);
+    CHECK_STATUS(task.status, ASUPERSYNC_STATUS_OK);
+
+    /* Nothing wakes the parked task, so a bounded wait times out. */
+    CHECK_STATUS(asupersync_region_wait(region.handle, 50),
+                 ASUPERSYNC_STATUS_TIMEOUT);
+    CHECK_STATUS(asupersync_task_checkpoint(task.handle), ASUPERSYNC_STATUS_OK);
+
+    /* Cancelling the region wakes the task, which then completes. */
+    CHECK_STATUS(asupersync_region_cancel(region.handle), ASUPERSYNC_STATUS_OK);
+    CHECK_STATUS(asupersync_region_wait(region.handle, ASUPERSYNC_WAIT_FOREVER),
+                 ASUPERSYNC_STATUS_OK);
+    CHECK(atomic_load(&parked.dropped) == 1);
+    CHECK(atomic_load(&parked.polls) >= 2);
+
+    /* Close cancels whatever is still live; the finished task is stale. */
+    struct counter_task second;
+    atomic_init(&second.polls, 0);
+    atomic_init(&second.dropped, 0);
+    second.target = 0;
+    task = asupersync_task_spawn(region.handle, poll_until_cancelled, &second,
+                                 drop_counter);
+    CHECK_STATUS(task.status, ASUPERSYNC_STATUS_OK);
+    CHECK_STATUS(asupersync_region_close(region.handle, 10000),
+                 ASUPERSYNC_STATUS_OK);
+    CHECK(atomic_load(&second.dropped) == 1);
+    CHECK_STATUS(asupersync_task_cancel(task.handle),
+                 ASUPERSYNC_STATUS_STALE_HANDLE);
+}
+
+static void handle_misuse(uint64_t runtime) {
+    AsupersyncHandleResult region = asupersync_region_open(runtime);
+    CHECK_STATUS(region.status, ASUPERSYNC_STATUS_OK);
+
+    /* Wrong kinds, zero and forged handles are invalid. */
+    CHECK_STATUS(asupersync_region_cancel(runtime),
+                 ASUPERSYNC_STATUS_INVALID_HANDLE);
+    CHECK_STATUS(asupersync_runtime_destroy(region.handle),
+                 ASUPERSYNC_STATUS_INVALID_HANDLE);
+    CHECK_STATUS(asupersync_task_wake(0), ASUPERSYNC_STATUS_INVALID_HANDLE);
+    CHECK_STATUS(asupersync_task_wake(UINT64_MAX),
+                 ASUPERSYNC_STATUS_INVALID_HANDLE);
+
+    /* Bad arguments are rejected; the context is still released. */
+    struct counter_task unused;
+    atomic_init(&unused.polls, 0);
+    atomic_init(&unused.dropped, 0);
+    unused.target = 1;
+    AsupersyncHandleResult task =
+        asupersync_task_spawn(region.handle, NULL, &unused, drop_counter);
+    CHECK_STATUS(task.status, ASUPERSYNC_STATUS_INVALID_ARGUMENT);
+    CHECK(task.handle == 0);
+    CHECK(atomic_load(&unused.dropped) == 1);
+    CHECK_STATUS(asupersync_log_set_callback(NULL, NULL, 99),
+                 ASUPERSYNC_STATUS_INVALID_ARGUMENT);
+
+    /* Double close and use-after-close are stale, not undefined. */
+    CHECK_STATUS(asupersync_region_close(region.handle, 1000),
+                 ASUPERSYNC_STATUS_OK);
+    CHECK_STATUS(asupersync_region_close(region.handle, 1000),
+                 ASUPERSYNC_STATUS_STALE_HANDLE);
+    task = asupersync_task_spawn(region.handle, poll_counter, &unused,
+                                 drop_counter);
+    CHECK_STATUS(task.status, ASUPERSYNC_STATUS_STALE_HANDLE);
+    CHECK(atomic_load(&unused.dropped) == 2);
+    CHECK(atomic_load(&unused.polls) == 0);
+}
+
+int main(void) {
+    static struct log_counts logs;
+    CHECK(asupersync_abi_version() == ASUPERSYNC_ABI_VERSION);
+    CHECK(strcmp(asupersync_status_name(ASUPERSYNC_STATUS_STALE_HANDLE),
+                 "stale_handle") == 0);
+    CHECK(strcmp(asupersync_status_name(-1), "unknown") == 0);
+    CHECK_STATUS(asupersync_log_set_callback(on_log, &logs,
+                                             ASUPERSYNC_LOG_LEVEL_DEBUG),
+                 ASUPERSYNC_STATUS_OK);
+
+    AsupersyncHandleResult runtime = asupersync_runtime_create(2);
+    CHECK_STATUS(runtime.status, ASUPERSYNC_STATUS_OK);
+
+    lifecycle(runtime.handle);
+    cancellation_and_timeout(runtime.handle);
+    handle_misuse(runtime.handle);
+
+    /* A panic is contained and reported, and the library stays usable. */
+    CHECK_STATUS(asupersync_diagnostic_panic(), ASUPERSYNC_STATUS_PANIC);
+    CHECK(atomic_load(&logs.panics) == 1);
+    AsupersyncHandleResult region = asupersync_region_open(runtime.handle);
+    CHECK_STATUS(region.status, ASUPERSYNC_STATUS_OK);
+    CHECK_STATUS(asupersync_region_close(region.handle, 1000),
+                 ASUPERSYNC_STATUS_OK);
+
+    CHECK_STATUS(asupersync_runtime_destroy(runtime.handle),
+                 ASUPERSYNC_STATUS_OK);
+    CHECK_STATUS(asupersync_runtime_destroy(runtime.handle),
+                 ASUPERSYNC_STATUS_STALE_HANDLE);
+
+    CHECK(atomic_load(&logs.records) > 0);
+    CHECK(atomic_load(&logs.warnings) > 0);
+    CHECK_STATUS(asupersync_log_set_callback(NULL, NULL, 0),
+                 ASUPERSYNC_STATUS_OK);
+    return 0;
+}
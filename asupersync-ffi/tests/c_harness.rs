@@ -0,0 +1,57 @@
+//! Builds `tests/c/harness.c` against the generated header and the freshly
+//! built shared library, runs it, and asserts it exits cleanly.
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `target/<profile>`, derived from this test binary's own location
+/// (`target/<profile>/deps/c_harness-<hash>`).
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().expect("current test executable");
+    exe.parent()
+        .and_then(Path::parent)
+        .expect("test binary lives under target/<profile>/deps")
+        .to_path_buf()
+}
+
+#[test]
+fn c_harness_exercises_the_abi() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = library_dir();
+    let out_dir =
+        std::env::temp_dir().join(format!("asupersync-ffi-harness-{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).expect("create harness output dir");
+
+    let compiler = cc::Build::new()
+        .target(env!("ASUPERSYNC_FFI_TARGET"))
+        .host(env!("ASUPERSYNC_FFI_HOST"))
+        .opt_level(0)
+        .cargo_metadata(false)
+        .get_compiler();
+    let harness = out_dir.join("asupersync_ffi_harness");
+    let status = compiler
+        .to_command()
+        .arg("-std=c11")
+        .arg("-I")
+        .arg(env!("ASUPERSYNC_FFI_INCLUDE_DIR"))
+        .arg(crate_dir.join("tests/c/harness.c"))
+        .arg("-o")
+        .arg(&harness)
+        .arg(format!("-L{}", lib_dir.display()))
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lasupersync_ffi")
+        .arg("-lpthread")
+        .status()
+        .expect("spawn C compiler");
+    assert!(status.success(), "compiling the C harness failed: {status}");
+
+    let output = Command::new(&harness).output().expect("run C harness");
+    assert!(
+        output.status.success(),
+        "C harness failed ({}):\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,33 @@
+//! Lists the direct Tokio dependencies left in a workspace.
+//!
+//! Usage: `tokio-compat-doctor [WORKSPACE_ROOT]` (defaults to the current
+//! directory). Exits with status 1 while any remain, so it can gate CI once a
+//! migration onto the shim is complete.
+
+#![forbid(unsafe_code)]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use asupersync_tokio_compat::doctor::scan_workspace;
+
+fn main() -> ExitCode {
+    let root = env::args_os()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("."), PathBuf::from);
+    match scan_workspace(&root) {
+        Ok(report) => {
+            print!("{report}");
+            if report.is_clean() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(err) => {
+            eprintln!("tokio-compat-doctor: {}: {err}", root.display());
+            ExitCode::from(2)
+        }
+    }
+}
@@ -244,7 +244,7 @@ where
 }
 
 /// Extract a human-readable message from a panic payload.
-fn panic_message(payload: &Box<dyn std::any::Any + Send>) -> String {
+pub(crate) fn panic_message(payload: &Box<dyn std::any::Any + Send>) -> String {
     payload.downcast_ref::<&str>().map_or_else(
         || {
             payload
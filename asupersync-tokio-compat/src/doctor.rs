@@ -0,0 +1,388 @@
+//! Finds the direct Tokio dependencies a workspace still declares.
+//!
+//! After switching a crate onto the [`shim`](crate::shim), the migration is
+//! done when no manifest names `tokio` (or a `tokio-*` crate) directly. The
+//! doctor walks a workspace, reads every `Cargo.toml`, and reports each such
+//! dependency with its manifest line, in the `path:line: message` form that
+//! editors and CI annotations pick up.
+//!
+//! The scan is line-based rather than a full TOML parse: it understands the
+//! dependency tables Cargo uses (`[dependencies]`, `[dev-dependencies]`,
+//! `[build-dependencies]`, their `[target.'cfg(..)'.*]` and
+//! `[workspace.dependencies]` forms, and `[dependencies.tokio]` sub-tables),
+//! and follows `package = "..."` renames. Dependencies renamed onto this
+//! crate, such as `tokio = { package = "asupersync-tokio-compat" }`, count as
+//! migrated.
+//!
+//! ```ignore
+//! let report = asupersync_tokio_compat::doctor::scan_workspace(".".as_ref())?;
+//! print!("{report}");
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const COMPAT_PACKAGE: &str = "asupersync-tokio-compat";
+
+/// One direct Tokio dependency found in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TokioDependency {
+    /// Manifest that declares the dependency.
+    pub manifest: PathBuf,
+    /// 1-based line of the declaration.
+    pub line: usize,
+    /// Dependency table, e.g. `dev-dependencies`.
+    pub section: String,
+    /// Crate depended on, after resolving `package = "..."` renames.
+    pub name: String,
+}
+
+impl fmt::Display for TokioDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: direct dependency on `{}` in [{}]",
+            self.manifest.display(),
+            self.line,
+            self.name,
+            self.section
+        )
+    }
+}
+
+/// Result of a doctor pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DoctorReport {
+    /// Number of manifests read.
+    pub manifests_scanned: usize,
+    /// Remaining direct Tokio dependencies, sorted by manifest and line.
+    pub dependencies: Vec<TokioDependency>,
+}
+
+impl DoctorReport {
+    /// Returns `true` when no direct Tokio dependency remains.
+    pub const fn is_clean(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for dependency in &self.dependencies {
+            writeln!(f, "{dependency}")?;
+        }
+        writeln!(
+            f,
+            "{} direct tokio dependencies in {} manifests",
+            self.dependencies.len(),
+            self.manifests_scanned
+        )
+    }
+}
+
+/// Scans every `Cargo.toml` under `root`, skipping `target` and hidden
+/// directories.
+///
+/// # Errors
+///
+/// Returns the first I/O error hit while walking `root` or reading a
+/// manifest.
+pub fn scan_workspace(root: &Path) -> io::Result<DoctorReport> {
+    let mut manifests = Vec::new();
+    collect_manifests(root, &mut manifests)?;
+    manifests.sort();
+
+    let mut report = DoctorReport::default();
+    for manifest in manifests {
+        let contents = fs::read_to_string(&manifest)?;
+        report.manifests_scanned += 1;
+        report.dependencies.extend(scan_manifest(&manifest, &contents));
+    }
+    Ok(report)
+}
+
+fn collect_manifests(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type()?.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_manifests(&path, out)?;
+            }
+        } else if name == "Cargo.toml" {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Scans one manifest's `contents`; `manifest` is only used for reporting.
+///
+/// The compat crate's own manifest is skipped, since it depends on Tokio by
+/// design.
+pub fn scan_manifest(manifest: &Path, contents: &str) -> Vec<TokioDependency> {
+    let mut found = Vec::new();
+    let mut table = Table::Other;
+    let mut sub_table: Option<SubTable> = None;
+
+    for (index, raw) in contents.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        if line.starts_with('[') {
+            if let Some(done) = sub_table.take() {
+                done.finish(manifest, &mut found);
+            }
+            table = Table::parse(line.trim_matches(|c| c == '[' || c == ']').trim());
+            if let Table::Dependency {
+                section,
+                key: Some(key),
+            } = &table
+            {
+                sub_table = Some(SubTable {
+                    section: section.clone(),
+                    key: key.clone(),
+                    line: index + 1,
+                    package: None,
+                });
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = unquote(key.trim());
+        let value = value.trim();
+        match &table {
+            Table::Package if key == "name" && unquote(value) == COMPAT_PACKAGE => {
+                return Vec::new();
+            }
+            Table::Dependency { key: Some(_), .. } if key == "package" => {
+                if let Some(sub_table) = sub_table.as_mut() {
+                    sub_table.package = Some(unquote(value).to_string());
+                }
+            }
+            Table::Dependency { section, key: None } => {
+                let name = inline_package(value).unwrap_or(key);
+                if is_tokio(name) {
+                    found.push(TokioDependency {
+                        manifest: manifest.to_path_buf(),
+                        line: index + 1,
+                        section: section.clone(),
+                        name: name.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(done) = sub_table {
+        done.finish(manifest, &mut found);
+    }
+    found
+}
+
+/// A `[dependencies.<key>]` table, resolved once its `package` key (if any)
+/// has been seen.
+struct SubTable {
+    section: String,
+    key: String,
+    line: usize,
+    package: Option<String>,
+}
+
+impl SubTable {
+    fn finish(self, manifest: &Path, found: &mut Vec<TokioDependency>) {
+        let name = self.package.unwrap_or(self.key);
+        if is_tokio(&name) {
+            found.push(TokioDependency {
+                manifest: manifest.to_path_buf(),
+                line: self.line,
+                section: self.section,
+                name,
+            });
+        }
+    }
+}
+
+enum Table {
+    Package,
+    Dependency {
+        section: String,
+        key: Option<String>,
+    },
+    Other,
+}
+
+impl Table {
+    fn parse(header: &str) -> Self {
+        if header == "package" {
+            return Self::Package;
+        }
+        let header = header
+            .strip_prefix("workspace.")
+            .or_else(|| target_suffix(header))
+            .unwrap_or(header);
+        for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            if header == section {
+                return Self::Dependency {
+                    section: section.to_string(),
+                    key: None,
+                };
+            }
+            if let Some(key) = header
+                .strip_prefix(section)
+                .and_then(|rest| rest.strip_prefix('.'))
+            {
+                return Self::Dependency {
+                    section: section.to_string(),
+                    key: Some(unquote(key).to_string()),
+                };
+            }
+        }
+        Self::Other
+    }
+}
+
+/// Strips a `target.<spec>.` prefix, where `<spec>` may be quoted and
+/// contain dots.
+fn target_suffix(header: &str) -> Option<&str> {
+    let rest = header.strip_prefix("target.")?;
+    let rest = match rest.chars().next()? {
+        quote @ ('\'' | '"') => {
+            let end = rest[1..].find(quote)? + 2;
+            &rest[end..]
+        }
+        _ => &rest[rest.find('.')?..],
+    };
+    rest.strip_prefix('.')
+}
+
+fn inline_package(value: &str) -> Option<&str> {
+    let start = value.find("package")?;
+    let rest = value[start + "package".len()..].trim_start().strip_prefix('=')?;
+    let rest = rest.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    Some(&rest[..rest.find(quote)?])
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '#') => return &line[..index],
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn is_tokio(name: &str) -> bool {
+    name != COMPAT_PACKAGE && (name == "tokio" || name.starts_with("tokio-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(contents: &str) -> Vec<(usize, String, String)> {
+        scan_manifest(Path::new("app/Cargo.toml"), contents)
+            .into_iter()
+            .map(|dep| (dep.line, dep.section, dep.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_finds_direct_tokio_dependencies() {
+        let manifest = r#"
+[package]
+name = "app"
+
+[dependencies]
+tokio = { version = "1", features = ["full"] } # runtime
+serde = "1"
+tokio-util = "0.7"
+
+[dev-dependencies]
+tokio-test = "0.4"
+
+[target.'cfg(unix)'.dependencies]
+rt = { package = "tokio", version = "1" }
+
+[workspace.dependencies]
+tokio-stream = "0.1"
+"#;
+        assert_eq!(
+            scan(manifest),
+            vec![
+                (6, "dependencies".to_string(), "tokio".to_string()),
+                (8, "dependencies".to_string(), "tokio-util".to_string()),
+                (11, "dev-dependencies".to_string(), "tokio-test".to_string()),
+                (14, "dependencies".to_string(), "tokio".to_string()),
+                (17, "dependencies".to_string(), "tokio-stream".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_follows_renames_and_sub_tables() {
+        let manifest = r#"
+[package]
+name = "app"
+
+[dependencies]
+tokio = { package = "asupersync-tokio-compat", version = "0.3" }
+asupersync-tokio-compat = "0.3"
+
+[dependencies.tokio-rustls]
+version = "0.26"
+
+[dev-dependencies.rt]
+package = "tokio"
+version = "1"
+
+[features]
+tokio = []
+"#;
+        assert_eq!(
+            scan(manifest),
+            vec![
+                (9, "dependencies".to_string(), "tokio-rustls".to_string()),
+                (12, "dev-dependencies".to_string(), "tokio".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_skips_compat_crate_manifest() {
+        let manifest = "[package]\nname = \"asupersync-tokio-compat\"\n\n\
+                        [dependencies]\ntokio = \"1\"\n";
+        assert!(scan(manifest).is_empty());
+    }
+
+    #[test]
+    fn test_report_display() {
+        let manifest = "[dependencies]\ntokio = \"1\"";
+        let report = DoctorReport {
+            manifests_scanned: 2,
+            dependencies: scan_manifest(Path::new("app/Cargo.toml"), manifest),
+        };
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.to_string(),
+            "app/Cargo.toml:2: direct dependency on `tokio` in [dependencies]\n\
+             1 direct tokio dependencies in 2 manifests\n"
+        );
+        assert!(DoctorReport::default().is_clean());
+    }
+}
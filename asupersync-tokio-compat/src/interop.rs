@@ -0,0 +1,592 @@
+//! Tokio runtime-context interop for futures that need a live Tokio runtime.
+//!
+//! Trait adapters are not enough for dependencies that call
+//! `tokio::time::sleep`, build `tokio::net` sockets, or `tokio::spawn` work of
+//! their own: those calls panic unless a Tokio runtime context is entered on
+//! the polling thread. This module provides two bridges:
+//!
+//! - [`TokioContext`] owns a private current-thread Tokio runtime on a
+//!   dedicated driver thread and enters its context around every poll of a
+//!   future passed to [`TokioContext::run`]. The future itself is still
+//!   polled by the Asupersync task awaiting it; only Tokio's timer and
+//!   reactor run on the driver thread.
+//! - [`spawn_tokio_owned`] is for futures that insist on `tokio::spawn`. The
+//!   future runs on an embedded current-thread runtime inside a region-owned
+//!   blocking task, and that task does not finish until every Tokio task the
+//!   future spawned has finished, so region close drains it.
+//!
+//! # Supported Tokio Features
+//!
+//! | Feature | Status |
+//! |---------|--------|
+//! | `tokio::time` (`sleep`, `timeout`, `interval`) | Supported |
+//! | `tokio::net` TCP (`TcpListener`, `TcpStream`) | Supported |
+//! | `tokio::net` UDP (`UdpSocket`) | Supported |
+//! | `tokio::spawn` | Only under [`spawn_tokio_owned`] |
+//! | Blocking pool (`spawn_blocking`, `block_in_place`, `tokio::fs`) | Unsupported |
+//! | `LocalSet` / `spawn_local` | Unsupported |
+//!
+//! Unsupported features fail with [`InteropError::Unsupported`] on the poll
+//! that observes them instead of hanging or leaving orphaned work behind.
+//! This crate does not enable Tokio's `fs` feature; if another dependency
+//! does, `tokio::fs` calls are reported as [`TokioFeature::BlockingPool`].
+//!
+//! # Drivers and Time
+//!
+//! Tokio's drivers are not pluggable, so the timer and reactor behind the
+//! shim are Tokio's own, driven by the shim's thread rather than by
+//! Asupersync's reactor. [`TokioClock::Paused`] starts Tokio's clock paused
+//! with auto-advance: whenever the shim runtime is idle its clock jumps to
+//! the next timer deadline, which gives lab tests deterministic virtual time
+//! for Tokio sleeps without waiting on the wall clock.
+
+use std::any::Any;
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+use asupersync::Cx;
+use asupersync::runtime::{SpawnError, TaskHandle};
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::sync::oneshot;
+
+use crate::blocking::panic_message;
+
+/// How often an embedded runtime re-checks its owner's cancellation state
+/// while the owned future is waiting on Tokio resources.
+const CANCEL_RECHECK_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A Tokio feature the interop bridges cannot provide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokioFeature {
+    /// `tokio::spawn` from a future run through [`TokioContext`]. Use
+    /// [`spawn_tokio_owned`] so the spawned tasks stay region-owned.
+    Spawn,
+    /// Tokio's blocking thread pool (`spawn_blocking`, `block_in_place`,
+    /// `tokio::fs`).
+    BlockingPool,
+    /// `LocalSet` and `spawn_local`.
+    LocalSet,
+}
+
+impl TokioFeature {
+    /// Stable name for diagnostics.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Spawn => "tokio::spawn",
+            Self::BlockingPool => "tokio blocking pool",
+            Self::LocalSet => "tokio LocalSet",
+        }
+    }
+
+    /// Map a Tokio panic to the unsupported feature that caused it.
+    fn from_panic(message: &str) -> Option<Self> {
+        if message.contains("spawn_local") || message.contains("LocalSet") {
+            Some(Self::LocalSet)
+        } else if message.contains("block_in_place") || message.contains("call blocking only") {
+            Some(Self::BlockingPool)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for TokioFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors returned by the Tokio interop bridges.
+#[derive(Debug)]
+pub enum InteropError {
+    /// The future used a Tokio feature the bridge cannot provide.
+    Unsupported {
+        /// The offending feature.
+        feature: TokioFeature,
+    },
+    /// Cancellation was requested on the owning `Cx` before the future
+    /// completed.
+    Cancelled,
+    /// Building the Tokio runtime or its driver thread failed.
+    Runtime(std::io::Error),
+}
+
+impl InteropError {
+    const fn unsupported(feature: TokioFeature) -> Self {
+        Self::Unsupported { feature }
+    }
+}
+
+impl fmt::Display for InteropError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported { feature } => {
+                write!(f, "unsupported tokio feature under asupersync interop: {feature}")
+            }
+            Self::Cancelled => write!(f, "tokio interop future cancelled"),
+            Self::Runtime(e) => write!(f, "failed to start tokio interop runtime: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InteropError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Runtime(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Clock used by a [`TokioContext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokioClock {
+    /// Tokio's timer follows the wall clock.
+    #[default]
+    Wall,
+    /// Tokio's clock starts paused and auto-advances to the next timer
+    /// deadline whenever the runtime is idle.
+    Paused,
+}
+
+/// Build a current-thread Tokio runtime whose blocking pool trips a latch.
+///
+/// `on_thread_start` only fires for blocking-pool threads on a
+/// current-thread runtime, so the latch records any use of the pool.
+fn build_runtime(clock: TokioClock) -> std::io::Result<(Runtime, Arc<AtomicBool>)> {
+    let blocking_pool_used = Arc::new(AtomicBool::new(false));
+    let latch = Arc::clone(&blocking_pool_used);
+    let runtime = Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .start_paused(clock == TokioClock::Paused)
+        .on_thread_start(move || latch.store(true, Ordering::Release))
+        .build()?;
+    Ok((runtime, blocking_pool_used))
+}
+
+/// Poll `future` once, translating Tokio's "unsupported here" panics into
+/// typed errors. Any other panic is propagated unchanged.
+fn poll_guarded<F: Future>(
+    future: std::pin::Pin<&mut F>,
+    poll_cx: &mut std::task::Context<'_>,
+) -> Poll<Result<F::Output, InteropError>> {
+    match catch_unwind(AssertUnwindSafe(|| future.poll(poll_cx))) {
+        Ok(poll) => poll.map(Ok),
+        Err(payload) => Poll::Ready(Err(unsupported_from_panic(payload))),
+    }
+}
+
+fn unsupported_from_panic(payload: Box<dyn Any + Send>) -> InteropError {
+    match TokioFeature::from_panic(&panic_message(&payload)) {
+        Some(feature) => InteropError::unsupported(feature),
+        None => resume_unwind(payload),
+    }
+}
+
+/// A Tokio runtime context entered around polls of wrapped futures.
+///
+/// The context owns a current-thread Tokio runtime parked on a dedicated
+/// driver thread; parking is what drives Tokio's timer and reactor. Futures
+/// passed to [`run`](Self::run) register timers and sockets with that
+/// runtime from whichever thread polls them. Dropping the context shuts the
+/// runtime down and joins the driver thread.
+///
+/// # Example
+///
+/// ```ignore
+/// use asupersync_tokio_compat::interop::{TokioClock, TokioContext};
+///
+/// let tokio = TokioContext::new(TokioClock::Wall)?;
+/// let body = tokio.run(&cx, dependency.fetch()).await?;
+/// ```
+pub struct TokioContext {
+    handle: Handle,
+    clock: TokioClock,
+    blocking_pool_used: Arc<AtomicBool>,
+    shutdown: Option<oneshot::Sender<()>>,
+    driver: Option<thread::JoinHandle<()>>,
+}
+
+impl TokioContext {
+    /// Start the shim runtime and its driver thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Tokio runtime or the driver thread cannot be
+    /// created.
+    pub fn new(clock: TokioClock) -> std::io::Result<Self> {
+        let (runtime, blocking_pool_used) = build_runtime(clock)?;
+        let handle = runtime.handle().clone();
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let driver = thread::Builder::new()
+            .name("asupersync-tokio-interop".to_string())
+            .spawn(move || {
+                let _ = runtime.block_on(shutdown_rx);
+            })?;
+        Ok(Self {
+            handle,
+            clock,
+            blocking_pool_used,
+            shutdown: Some(shutdown),
+            driver: Some(driver),
+        })
+    }
+
+    /// The Tokio handle for dependencies that take one explicitly.
+    ///
+    /// Only hand this to code that uses it for timers and I/O; tasks spawned
+    /// through it are not region-owned. Use [`spawn_tokio_owned`] instead.
+    #[must_use]
+    pub const fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// The clock this context was started with.
+    #[must_use]
+    pub const fn clock(&self) -> TokioClock {
+        self.clock
+    }
+
+    /// Drive `future` to completion with this Tokio context entered on every
+    /// poll.
+    ///
+    /// # Errors
+    ///
+    /// - [`InteropError::Cancelled`] if cancellation is requested on `cx`
+    ///   before the future completes (observed at poll boundaries).
+    /// - [`InteropError::Unsupported`] if the future calls `tokio::spawn`,
+    ///   uses Tokio's blocking pool, or uses `LocalSet`/`spawn_local`. The
+    ///   future is dropped; tasks it spawned stay on the shim runtime until
+    ///   this context is dropped.
+    pub async fn run<F>(&self, cx: &Cx, future: F) -> Result<F::Output, InteropError>
+    where
+        F: Future,
+    {
+        let mut future = pin!(future);
+        poll_fn(|poll_cx| {
+            if cx.is_cancel_requested() {
+                return Poll::Ready(Err(InteropError::Cancelled));
+            }
+            if self.blocking_pool_used.load(Ordering::Acquire) {
+                return Poll::Ready(Err(InteropError::unsupported(TokioFeature::BlockingPool)));
+            }
+
+            let _enter = self.handle.enter();
+            let tasks_before = self.handle.metrics().num_alive_tasks();
+            let poll = poll_guarded(future.as_mut(), poll_cx);
+            if self.handle.metrics().num_alive_tasks() > tasks_before {
+                return Poll::Ready(Err(InteropError::unsupported(TokioFeature::Spawn)));
+            }
+            poll
+        })
+        .await
+    }
+}
+
+impl fmt::Debug for TokioContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokioContext")
+            .field("clock", &self.clock)
+            .field("blocking_pool_used", &self.blocking_pool_used.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for TokioContext {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(driver) = self.driver.take() {
+            let _ = driver.join();
+        }
+    }
+}
+
+/// Run a future that needs `tokio::spawn` on an embedded Tokio runtime owned
+/// by `cx`'s region.
+///
+/// The future runs on a fresh current-thread runtime inside a blocking task
+/// spawned into the caller's region, with `tokio::spawn`,
+/// `Handle::current()`, timers and sockets all available. After the future
+/// completes, the task keeps driving the runtime until every Tokio task it
+/// spawned has finished, so the region cannot close while embedded work is
+/// still alive. If the owner is cancelled, the embedded runtime is torn down
+/// within a few milliseconds and its remaining tasks are dropped.
+///
+/// # Errors
+///
+/// Returns [`SpawnError`] if the region task cannot be spawned. The handle
+/// resolves to [`InteropError::Cancelled`] on cancellation,
+/// [`InteropError::Unsupported`] if the future uses Tokio's blocking pool or
+/// `LocalSet`, and [`InteropError::Runtime`] if the runtime cannot be built.
+pub fn spawn_tokio_owned<F>(
+    cx: &Cx,
+    future: F,
+) -> Result<TaskHandle<Result<F::Output, InteropError>>, SpawnError>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    cx.spawn_blocking(move |owner| run_embedded(&owner, future))
+}
+
+fn run_embedded<F: Future>(owner: &Cx, future: F) -> Result<F::Output, InteropError> {
+    let (runtime, blocking_pool_used) =
+        build_runtime(TokioClock::Wall).map_err(InteropError::Runtime)?;
+    let handle = runtime.handle().clone();
+    let interrupted = || {
+        if owner.is_cancel_requested() {
+            Some(InteropError::Cancelled)
+        } else if blocking_pool_used.load(Ordering::Acquire) {
+            Some(InteropError::unsupported(TokioFeature::BlockingPool))
+        } else {
+            None
+        }
+    };
+
+    let result = runtime.block_on(async {
+        let mut future = pin!(future);
+        // Keeps a timer armed so cancellation is noticed even while the
+        // future waits on something that never wakes it.
+        let mut recheck = tokio::time::interval(CANCEL_RECHECK_INTERVAL);
+
+        let output = poll_fn(|poll_cx| {
+            if let Some(err) = interrupted() {
+                return Poll::Ready(Err(err));
+            }
+            while recheck.poll_tick(poll_cx).is_ready() {}
+            poll_guarded(future.as_mut(), poll_cx)
+        })
+        .await?;
+
+        // The future is done, but the tasks it spawned still belong to the
+        // owning region.
+        poll_fn(|poll_cx| {
+            if let Some(err) = interrupted() {
+                return Poll::Ready(Err(err));
+            }
+            if handle.metrics().num_alive_tasks() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+            while recheck.poll_tick(poll_cx).is_ready() {}
+            Poll::Pending
+        })
+        .await?;
+
+        Ok(output)
+    });
+
+    // Dropping the runtime drops any task left behind by cancellation or an
+    // unsupported feature before the owning task reports completion.
+    drop(runtime);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asupersync::runtime::RuntimeBuilder;
+    use futures_lite::future::{block_on, zip};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn context(clock: TokioClock) -> TokioContext {
+        TokioContext::new(clock).expect("tokio context")
+    }
+
+    #[test]
+    fn tokio_sleep_runs_on_virtual_time() {
+        let tokio = context(TokioClock::Paused);
+        let cx = Cx::for_testing();
+        let wall = Instant::now();
+
+        let (slept, timed_out) = block_on(tokio.run(&cx, async {
+            let start = tokio::time::Instant::now();
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            let slept = start.elapsed();
+            let timed_out = tokio::time::timeout(
+                Duration::from_secs(10),
+                tokio::time::sleep(Duration::from_secs(20)),
+            )
+            .await
+            .is_err();
+            (slept, timed_out)
+        }))
+        .expect("run");
+
+        assert!(slept >= Duration::from_secs(3600), "slept {slept:?}");
+        assert!(timed_out, "earlier deadline fires first");
+        assert!(wall.elapsed() < Duration::from_secs(30), "virtual time must not wait");
+    }
+
+    #[test]
+    fn tokio_tcp_echo_through_shim() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+        let listener = block_on(tokio.run(&cx, tokio::net::TcpListener::bind("127.0.0.1:0")))
+            .expect("run")
+            .expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+
+        let server = tokio.run(&cx, async move {
+            let (mut stream, _) = listener.accept().await?;
+            let mut buf = [0_u8; 5];
+            stream.read_exact(&mut buf).await?;
+            stream.write_all(&buf).await?;
+            Ok::<_, std::io::Error>(())
+        });
+        let client = tokio.run(&cx, async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await?;
+            stream.write_all(b"hello").await?;
+            let mut buf = [0_u8; 5];
+            stream.read_exact(&mut buf).await?;
+            Ok::<_, std::io::Error>(buf)
+        });
+
+        let (server, client) = block_on(zip(server, client));
+        server.expect("run").expect("server");
+        assert_eq!(&client.expect("run").expect("client"), b"hello");
+    }
+
+    #[test]
+    fn tokio_udp_round_trip_through_shim() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+
+        let echoed = block_on(tokio.run(&cx, async {
+            let a = tokio::net::UdpSocket::bind("127.0.0.1:0").await?;
+            let b = tokio::net::UdpSocket::bind("127.0.0.1:0").await?;
+            a.send_to(b"ping", b.local_addr()?).await?;
+            let mut buf = [0_u8; 8];
+            let (len, from) = b.recv_from(&mut buf).await?;
+            b.send_to(&buf[..len], from).await?;
+            let len = a.recv(&mut buf).await?;
+            Ok::<_, std::io::Error>(buf[..len].to_vec())
+        }))
+        .expect("run")
+        .expect("udp");
+
+        assert_eq!(echoed, b"ping");
+    }
+
+    #[test]
+    fn cancelled_cx_stops_wrapped_future() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+        cx.set_cancel_requested(true);
+
+        let result = block_on(tokio.run(&cx, async { 1_u8 }));
+        assert!(matches!(result, Err(InteropError::Cancelled)), "{result:?}");
+    }
+
+    #[test]
+    fn stray_tokio_spawn_is_a_typed_error() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+
+        let result = block_on(tokio.run(&cx, async {
+            tokio::spawn(std::future::pending::<()>());
+        }));
+        assert!(
+            matches!(
+                result,
+                Err(InteropError::Unsupported {
+                    feature: TokioFeature::Spawn
+                })
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn spawn_local_is_a_typed_error() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+
+        let result = block_on(tokio.run(&cx, async {
+            drop(tokio::task::spawn_local(async {}));
+        }));
+        assert!(
+            matches!(
+                result,
+                Err(InteropError::Unsupported {
+                    feature: TokioFeature::LocalSet
+                })
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn blocking_pool_is_a_typed_error() {
+        let tokio = context(TokioClock::Wall);
+        let cx = Cx::for_testing();
+
+        let result = block_on(tokio.run(&cx, tokio::task::spawn_blocking(|| 1_u8)));
+        let err = result.expect_err("blocking pool is unsupported");
+        assert!(
+            matches!(
+                err,
+                InteropError::Unsupported {
+                    feature: TokioFeature::BlockingPool
+                }
+            ),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("tokio blocking pool"), "{err}");
+    }
+
+    #[test]
+    fn owned_runtime_drains_spawned_tasks_before_completing() {
+        let runtime = RuntimeBuilder::current_thread().build().expect("runtime");
+        let finished = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&finished);
+
+        let result = runtime.block_on(runtime.handle().spawn(async move {
+            let cx = Cx::current().expect("runtime task context");
+            let mut owned = spawn_tokio_owned(&cx, async move {
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    flag.store(true, Ordering::SeqCst);
+                });
+                7_u32
+            })
+            .expect("spawn owned");
+            owned.join(&cx).await
+        }));
+
+        assert_eq!(result.expect("join").expect("owned"), 7);
+        assert!(
+            finished.load(Ordering::SeqCst),
+            "owning task must outlive the embedded tokio tasks"
+        );
+    }
+
+    #[test]
+    fn owned_runtime_observes_cancellation() {
+        let runtime = RuntimeBuilder::current_thread().build().expect("runtime");
+
+        let result = runtime.block_on(runtime.handle().spawn(async move {
+            let cx = Cx::current().expect("runtime task context");
+            let mut owned =
+                spawn_tokio_owned(&cx, std::future::pending::<()>()).expect("spawn owned");
+            owned.abort();
+            owned.join(&cx).await
+        }));
+
+        assert!(
+            !matches!(result, Ok(Ok(()))),
+            "a pending future only ends through cancellation: {result:?}"
+        );
+    }
+}
@@ -33,8 +33,17 @@
 //! |---------|---------|
 //! | `hyper-bridge` | hyper v1 runtime trait implementations |
 //! | `tokio-io` | Bidirectional `AsyncRead`/`AsyncWrite` adapters |
+//! | `tokio-interop` | Tokio runtime-context shim and region-owned embedded Tokio runtimes |
 //! | `full` | All adapters |
 //!
+//! # Migrating Off Tokio
+//!
+//! The [`shim`] module mirrors the common `tokio::{spawn, time, sync, io,
+//! net}` surface on Asupersync, so a Tokio crate can switch runtimes with a
+//! dependency swap and a `use asupersync_tokio_compat::shim as tokio;` alias.
+//! The [`doctor`] pass (also the `tokio-compat-doctor` binary) lists the
+//! direct Tokio dependencies a workspace still has.
+//!
 //! # Hard Boundary Rules
 //!
 //! 1. The main `asupersync` crate does NOT depend on this crate (one-way dep).
@@ -57,8 +66,10 @@ pub const OWNER_TRACK_ID: &str = "asupersync-2oh2u.7";
 
 pub mod blocking;
 pub mod cancel;
+pub mod doctor;
 pub mod io;
 pub mod runtime;
+pub mod shim;
 
 #[cfg(feature = "hyper-bridge")]
 pub mod hyper_bridge;
@@ -69,6 +80,9 @@ pub mod body_bridge;
 #[cfg(feature = "tower-bridge")]
 pub mod tower_bridge;
 
+#[cfg(feature = "tokio-interop")]
+pub mod interop;
+
 /// Cancellation mode for adapter-wrapped futures.
 ///
 /// Controls how Asupersync cancellation interacts with Tokio-originated futures
@@ -0,0 +1,165 @@
+//! Drop-in facade for the most common Tokio APIs, implemented on Asupersync.
+//!
+//! Porting a Tokio service call site by call site stalls on the first few
+//! hundred `tokio::spawn`s. This module mirrors the everyday Tokio surface so
+//! a crate can switch runtimes first and migrate to native Asupersync APIs
+//! incrementally:
+//!
+//! ```ignore
+//! // Before: `tokio = { version = "1", features = ["full"] }`
+//! // After:  `asupersync-tokio-compat = "0.3"`, plus one alias per crate:
+//! use asupersync_tokio_compat::shim as tokio;
+//! ```
+//!
+//! `#[tokio::main]` and `#[tokio::test]` have no shim; build an Asupersync
+//! runtime and `block_on` the entry future instead. The
+//! [`doctor`](crate::doctor) pass lists the direct Tokio dependencies a
+//! workspace still has.
+//!
+//! # Coverage
+//!
+//! | Tokio | Shim | Backed by |
+//! |-------|------|-----------|
+//! | `spawn`, `task::{JoinHandle, JoinError, AbortHandle, yield_now}` | [`task`] | compat region |
+//! | `time::{sleep, sleep_until, timeout, interval, interval_at}` | [`time`] | `asupersync::time` |
+//! | `sync::{mpsc, oneshot}` | [`sync::mpsc`], [`sync::oneshot`] | `asupersync::channel` |
+//! | `sync::{Mutex, RwLock, Semaphore}` | [`sync`] | `asupersync::sync` |
+//! | `io::{AsyncRead, AsyncWrite, AsyncReadExt, AsyncWriteExt}` | [`io`] | `asupersync::io` |
+//! | `net::{TcpListener, TcpStream, UdpSocket}` | [`net`] | `asupersync::net` |
+//!
+//! # Where Semantics Differ
+//!
+//! - **Spawned tasks are owned.** [`spawn`] places tasks in a region owned by
+//!   the runtime, which drains them at shutdown instead of dropping them.
+//!   See [`task`].
+//! - **Abort is a cancel request.** [`JoinHandle::abort`](task::JoinHandle::abort)
+//!   drops the task's future at its next poll rather than synchronously.
+//! - **Cancel-aware waits.** Channel sends and receives and semaphore
+//!   acquires resolve as closed when the waiting task is cancelled.
+//! - **Poisoning.** A panic while holding a shim lock poisons it, and later
+//!   lock attempts panic.
+//! - **Instants.** Time APIs use Asupersync's [`Time`](time::Time) rather
+//!   than `tokio::time::Instant`.
+//!
+//! Each divergence logs a structured warning the first time a process hits
+//! it, and [`reported_divergences`] lists the ones seen so far.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Mutex;
+
+use asupersync::Cx;
+
+pub mod io;
+pub mod net;
+pub mod sync;
+pub mod task;
+pub mod time;
+
+pub use task::spawn;
+
+/// A place where the shim cannot preserve Tokio's semantics exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Divergence {
+    /// `JoinHandle::abort` / `AbortHandle::abort`.
+    Abort,
+    /// A channel or semaphore wait ended because the task was cancelled.
+    CancelledWait,
+    /// A shim lock was poisoned by a panicking holder.
+    Poisoned,
+}
+
+impl Divergence {
+    /// The Tokio API this divergence affects.
+    pub const fn api(self) -> &'static str {
+        match self {
+            Self::Abort => "tokio::task::JoinHandle::abort",
+            Self::CancelledWait => "tokio::sync waits",
+            Self::Poisoned => "tokio::sync::{Mutex, RwLock}",
+        }
+    }
+
+    /// How the shim behaves differently.
+    pub const fn detail(self) -> &'static str {
+        match self {
+            Self::Abort => {
+                "abort requests cancellation; the future is dropped at its next poll, \
+                 not synchronously"
+            }
+            Self::CancelledWait => {
+                "the waiting task was cancelled, so the wait resolved as if the \
+                 channel or semaphore were closed"
+            }
+            Self::Poisoned => "a task panicked while holding the lock; later lock attempts panic",
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.api(), self.detail())
+    }
+}
+
+static REPORTED: Mutex<BTreeSet<Divergence>> = Mutex::new(BTreeSet::new());
+
+/// Logs `divergence` the first time this process hits it.
+pub(crate) fn warn_divergence(divergence: Divergence) {
+    let first = REPORTED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(divergence);
+    if first {
+        asupersync::tracing_compat::warn!(
+            api = divergence.api(),
+            detail = divergence.detail(),
+            "tokio shim diverges from tokio semantics"
+        );
+    }
+}
+
+/// Returns the divergences this process has hit so far, in a stable order.
+pub fn reported_divergences() -> Vec<Divergence> {
+    REPORTED
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .iter()
+        .copied()
+        .collect()
+}
+
+/// Returns the calling task's `Cx` for a cancel-aware wait.
+pub(crate) fn current_cx(api: &'static str) -> Cx {
+    Cx::current().unwrap_or_else(|| {
+        panic!("tokio shim `{api}` must be awaited from within an asupersync task")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divergence_is_reported_once() {
+        warn_divergence(Divergence::Poisoned);
+        warn_divergence(Divergence::Poisoned);
+        let reported = reported_divergences();
+        let poisoned = reported
+            .iter()
+            .filter(|divergence| **divergence == Divergence::Poisoned)
+            .count();
+        assert_eq!(poisoned, 1);
+        assert!(
+            Divergence::Abort
+                .to_string()
+                .starts_with("tokio::task::JoinHandle::abort: ")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must be awaited from within an asupersync task")]
+    fn test_cancel_aware_wait_outside_task_panics() {
+        let (_tx, mut rx) = sync::mpsc::channel::<u8>(1);
+        futures_lite::future::block_on(rx.recv());
+    }
+}
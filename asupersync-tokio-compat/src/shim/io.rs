@@ -0,0 +1,18 @@
+//! `tokio::io` equivalents: Asupersync's I/O traits under Tokio's names.
+//!
+//! `AsyncRead`/`AsyncWrite` and their extension traits keep Tokio's method
+//! names (`read`, `read_exact`, `read_to_end`, `write_all`, `flush`,
+//! `shutdown`), so code that only imports and calls them ports unchanged.
+//! Hand-written `poll_read`/`poll_write` impls also carry over: both traits
+//! take a [`ReadBuf`] or byte slice in the same positions as Tokio's.
+//!
+//! To hand a shim stream to a crate that requires the real Tokio traits,
+//! wrap it in [`TokioIo`](crate::io::TokioIo); to use a real Tokio stream
+//! here, wrap it in [`AsupersyncIo`](crate::io::AsupersyncIo). Both need the
+//! `tokio-io` feature.
+
+pub use asupersync::io::{
+    AsyncBufRead, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter,
+    ReadBuf, copy,
+};
+pub use std::io::{Error, ErrorKind, Result};
@@ -0,0 +1,11 @@
+//! `tokio::net` equivalents on Asupersync's reactor-backed sockets.
+//!
+//! Asupersync's TCP types already follow Tokio's shape (`bind`, `accept`,
+//! `connect`, `split`, `into_split`), so they are re-exported as is.
+
+pub use asupersync::net::{TcpListener, TcpStream, UdpSocket};
+
+/// TCP stream halves, like `tokio::net::tcp`.
+pub mod tcp {
+    pub use asupersync::net::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
+}
@@ -0,0 +1,345 @@
+//! `tokio::sync` equivalents on Asupersync's synchronization primitives.
+//!
+//! Lock waits ([`Mutex`], [`RwLock`]) are uninterruptible, as in Tokio, so
+//! a cancelled task still gets the guard it was queued for. Channel and
+//! semaphore waits are cancel-aware: once the calling task is cancelled they
+//! resolve as closed, which ends the usual `while let Some(..) = rx.recv()`
+//! loop instead of leaving the task parked while its region drains.
+//!
+//! Asupersync locks are poisoned by a panic while a guard is held; Tokio's
+//! are not. The shim panics when it meets a poisoned lock rather than hand
+//! out state that a panicking task left half-updated.
+
+use std::fmt;
+use std::sync::Arc;
+
+use asupersync::Cx;
+use asupersync::sync::{
+    AcquireError as InnerAcquireError, TryLockError as InnerTryLockError, TryReadError,
+    TryWriteError,
+};
+
+pub use asupersync::sync::{
+    MutexGuard, OwnedSemaphorePermit, RwLockReadGuard, RwLockWriteGuard, SemaphorePermit,
+};
+
+use super::{Divergence, current_cx, warn_divergence};
+
+pub mod mpsc;
+pub mod oneshot;
+
+fn poisoned(api: &'static str) -> ! {
+    warn_divergence(Divergence::Poisoned);
+    panic!("tokio shim `{api}`: lock poisoned by a task that panicked while holding it");
+}
+
+/// An async mutex, like `tokio::sync::Mutex`.
+#[derive(Debug)]
+pub struct Mutex<T> {
+    inner: asupersync::sync::Mutex<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Creates an unlocked mutex holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: asupersync::sync::Mutex::new(value),
+        }
+    }
+
+    /// Waits for and returns the lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        let cx = Cx::detached_cancel_context();
+        match self.inner.lock(&cx).await {
+            Ok(guard) => guard,
+            Err(_) => poisoned("Mutex::lock"),
+        }
+    }
+
+    /// Takes the lock if it is free.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned.
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, TryLockError> {
+        match self.inner.try_lock() {
+            Ok(guard) => Ok(guard),
+            Err(InnerTryLockError::Locked) => Err(TryLockError(())),
+            Err(InnerTryLockError::Poisoned) => poisoned("Mutex::try_lock"),
+        }
+    }
+
+    /// Returns a mutable reference to the value; no locking is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner
+            .get_mut()
+            .unwrap_or_else(|_| poisoned("Mutex::get_mut"))
+    }
+
+    /// Consumes the mutex and returns the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned.
+    pub fn into_inner(self) -> T {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(|_| poisoned("Mutex::into_inner"))
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// An async reader-writer lock, like `tokio::sync::RwLock`.
+#[derive(Debug)]
+pub struct RwLock<T> {
+    inner: asupersync::sync::RwLock<T>,
+}
+
+impl<T> RwLock<T> {
+    /// Creates an unlocked lock holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: asupersync::sync::RwLock::new(value),
+        }
+    }
+
+    /// Waits for shared read access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        let cx = Cx::detached_cancel_context();
+        match self.inner.read(&cx).await {
+            Ok(guard) => guard,
+            Err(_) => poisoned("RwLock::read"),
+        }
+    }
+
+    /// Waits for exclusive write access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let cx = Cx::detached_cancel_context();
+        match self.inner.write(&cx).await {
+            Ok(guard) => guard,
+            Err(_) => poisoned("RwLock::write"),
+        }
+    }
+
+    /// Takes read access if no writer holds or awaits the lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub fn try_read(&self) -> Result<RwLockReadGuard<'_, T>, TryLockError> {
+        match self.inner.try_read() {
+            Ok(guard) => Ok(guard),
+            Err(TryReadError::Locked) => Err(TryLockError(())),
+            Err(TryReadError::Poisoned) => poisoned("RwLock::try_read"),
+        }
+    }
+
+    /// Takes write access if the lock is free.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, TryLockError> {
+        match self.inner.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryWriteError::Locked) => Err(TryLockError(())),
+            Err(TryWriteError::Poisoned) => poisoned("RwLock::try_write"),
+        }
+    }
+
+    /// Returns a mutable reference to the value; no locking is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner
+            .get_mut()
+            .unwrap_or_else(|_| poisoned("RwLock::get_mut"))
+    }
+
+    /// Consumes the lock and returns the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned.
+    pub fn into_inner(self) -> T {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(|_| poisoned("RwLock::into_inner"))
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A counting semaphore, like `tokio::sync::Semaphore`.
+#[derive(Debug)]
+pub struct Semaphore {
+    inner: Arc<asupersync::sync::Semaphore>,
+}
+
+impl Semaphore {
+    /// Creates a semaphore with `permits` permits.
+    pub fn new(permits: usize) -> Self {
+        Self {
+            inner: Arc::new(asupersync::sync::Semaphore::new(permits)),
+        }
+    }
+
+    /// Returns the number of permits available now.
+    pub fn available_permits(&self) -> usize {
+        self.inner.available_permits()
+    }
+
+    /// Adds `n` permits.
+    pub fn add_permits(&self, n: usize) {
+        self.inner.add_permits(n);
+    }
+
+    /// Closes the semaphore; pending and future acquires fail.
+    pub fn close(&self) {
+        self.inner.close();
+    }
+
+    /// Returns `true` once the semaphore is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Waits for one permit.
+    ///
+    /// Fails if the semaphore is closed, or if the calling task is cancelled
+    /// while waiting.
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Waits for `n` permits, acquired all at once. See [`Self::acquire`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn acquire_many(&self, n: u32) -> Result<SemaphorePermit<'_>, AcquireError> {
+        let cx = current_cx("Semaphore::acquire");
+        let result = self.inner.acquire(&cx, n as usize).await;
+        result.map_err(AcquireError::from_inner)
+    }
+
+    /// Takes one permit if available.
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.inner
+            .try_acquire(1)
+            .map_err(|_| TryAcquireError::from_state(&self.inner))
+    }
+
+    /// Waits for one permit that is not tied to a borrow of the semaphore.
+    /// See [`Self::acquire`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn acquire_owned(self: Arc<Self>) -> Result<OwnedSemaphorePermit, AcquireError> {
+        let cx = current_cx("Semaphore::acquire_owned");
+        let semaphore = Arc::clone(&self.inner);
+        let result = OwnedSemaphorePermit::acquire(semaphore, &cx, 1).await;
+        result.map_err(AcquireError::from_inner)
+    }
+
+    /// Takes one owned permit if available.
+    pub fn try_acquire_owned(self: Arc<Self>) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        OwnedSemaphorePermit::try_acquire_arc(&self.inner, 1)
+            .map_err(|_| TryAcquireError::from_state(&self.inner))
+    }
+}
+
+/// The lock was held; returned by the `try_*` lock methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryLockError(());
+
+impl fmt::Display for TryLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation would block")
+    }
+}
+
+impl std::error::Error for TryLockError {}
+
+/// The semaphore was closed, or the waiting task was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquireError(());
+
+impl AcquireError {
+    fn from_inner(err: InnerAcquireError) -> Self {
+        if err == InnerAcquireError::Cancelled {
+            warn_divergence(Divergence::CancelledWait);
+        }
+        Self(())
+    }
+}
+
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("semaphore closed")
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+/// A permit could not be taken without waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore is closed.
+    Closed,
+    /// No permits are available.
+    NoPermits,
+}
+
+impl TryAcquireError {
+    fn from_state(semaphore: &asupersync::sync::Semaphore) -> Self {
+        if semaphore.is_closed() {
+            Self::Closed
+        } else {
+            Self::NoPermits
+        }
+    }
+}
+
+impl fmt::Display for TryAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("semaphore closed"),
+            Self::NoPermits => f.write_str("no permits available"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
@@ -0,0 +1,271 @@
+//! Multi-producer, single-consumer channels, like `tokio::sync::mpsc`.
+//!
+//! Bounded sends use Asupersync's two-phase reserve/commit, so a send that
+//! is cancelled while waiting for capacity never loses or duplicates the
+//! value: it comes back in the [`SendError`](error::SendError).
+
+use asupersync::channel::mpsc as inner;
+use asupersync::channel::mpsc::{RecvError, SendError as InnerSendError};
+
+use self::error::{SendError, TryRecvError, TrySendError};
+use crate::shim::{Divergence, current_cx, warn_divergence};
+
+/// Channel error types, like `tokio::sync::mpsc::error`.
+pub mod error {
+    use std::fmt;
+
+    /// The receiver is gone (or the sending task was cancelled); the value is
+    /// returned.
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    pub struct SendError<T>(pub T);
+
+    impl<T> fmt::Debug for SendError<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SendError").finish_non_exhaustive()
+        }
+    }
+
+    impl<T> fmt::Display for SendError<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("channel closed")
+        }
+    }
+
+    impl<T> std::error::Error for SendError<T> {}
+
+    /// A non-waiting send failed; the value is returned.
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    pub enum TrySendError<T> {
+        /// The channel is at capacity.
+        Full(T),
+        /// The receiver is gone.
+        Closed(T),
+    }
+
+    impl<T> TrySendError<T> {
+        /// Returns the value that could not be sent.
+        pub fn into_inner(self) -> T {
+            match self {
+                Self::Full(value) | Self::Closed(value) => value,
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for TrySendError<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Full(_) => f.write_str("Full(..)"),
+                Self::Closed(_) => f.write_str("Closed(..)"),
+            }
+        }
+    }
+
+    impl<T> fmt::Display for TrySendError<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Full(_) => f.write_str("no available capacity"),
+                Self::Closed(_) => f.write_str("channel closed"),
+            }
+        }
+    }
+
+    impl<T> std::error::Error for TrySendError<T> {}
+
+    /// A non-waiting receive found nothing.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum TryRecvError {
+        /// No message is queued, but senders remain.
+        Empty,
+        /// No message is queued and every sender is gone.
+        Disconnected,
+    }
+
+    impl fmt::Display for TryRecvError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Empty => f.write_str("receiving on an empty channel"),
+                Self::Disconnected => f.write_str("receiving on a closed channel"),
+            }
+        }
+    }
+
+    impl std::error::Error for TryRecvError {}
+}
+
+fn recv_result<T>(result: Result<T, RecvError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(RecvError::Cancelled) => {
+            warn_divergence(Divergence::CancelledWait);
+            None
+        }
+        Err(RecvError::Disconnected | RecvError::Empty) => None,
+    }
+}
+
+fn try_recv_result<T>(result: Result<T, RecvError>) -> Result<T, TryRecvError> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(RecvError::Empty) => Err(TryRecvError::Empty),
+        Err(RecvError::Disconnected | RecvError::Cancelled) => Err(TryRecvError::Disconnected),
+    }
+}
+
+/// Creates a bounded channel holding up to `buffer` messages.
+///
+/// # Panics
+///
+/// Panics if `buffer` is zero, as Tokio does.
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+    let (tx, rx) = inner::channel(buffer);
+    (Sender { inner: tx }, Receiver { inner: rx })
+}
+
+/// Creates an unbounded channel.
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let (tx, rx) = inner::unbounded_channel();
+    (UnboundedSender { inner: tx }, UnboundedReceiver { inner: rx })
+}
+
+/// Sending half of a bounded channel.
+#[derive(Debug)]
+pub struct Sender<T> {
+    inner: inner::Sender<T>,
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Waits for capacity and sends `value`.
+    ///
+    /// Fails if the receiver is gone, or if the calling task is cancelled
+    /// while waiting for capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let cx = current_cx("mpsc::Sender::send");
+        match self.inner.send(&cx, value).await {
+            Ok(()) => Ok(()),
+            Err(InnerSendError::Cancelled(value)) => {
+                warn_divergence(Divergence::CancelledWait);
+                Err(SendError(value))
+            }
+            Err(InnerSendError::Disconnected(value) | InnerSendError::Full(value)) => {
+                Err(SendError(value))
+            }
+        }
+    }
+
+    /// Sends `value` if there is capacity now.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(value).map_err(|err| match err {
+            InnerSendError::Full(value) => TrySendError::Full(value),
+            InnerSendError::Disconnected(value) | InnerSendError::Cancelled(value) => {
+                TrySendError::Closed(value)
+            }
+        })
+    }
+
+    /// Returns `true` once the receiver is gone.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+/// Receiving half of a bounded channel.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    inner: inner::Receiver<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Receives the next message, or `None` once every sender is gone.
+    ///
+    /// Also returns `None` if the calling task is cancelled while waiting.
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn recv(&mut self) -> Option<T> {
+        let cx = current_cx("mpsc::Receiver::recv");
+        recv_result(self.inner.recv(&cx).await)
+    }
+
+    /// Receives a message if one is queued.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        try_recv_result(self.inner.try_recv())
+    }
+
+    /// Closes the channel to new messages; queued ones can still be received.
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+/// Sending half of an unbounded channel.
+#[derive(Debug)]
+pub struct UnboundedSender<T> {
+    inner: inner::UnboundedSender<T>,
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// Sends `value` without waiting.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value).map_err(|err| match err {
+            InnerSendError::Disconnected(value)
+            | InnerSendError::Cancelled(value)
+            | InnerSendError::Full(value) => SendError(value),
+        })
+    }
+
+    /// Returns `true` once the receiver is gone.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+/// Receiving half of an unbounded channel.
+#[derive(Debug)]
+pub struct UnboundedReceiver<T> {
+    inner: inner::UnboundedReceiver<T>,
+}
+
+impl<T> UnboundedReceiver<T> {
+    /// Receives the next message, or `None` once every sender is gone.
+    /// See [`Receiver::recv`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when awaited outside an Asupersync task.
+    pub async fn recv(&mut self) -> Option<T> {
+        let cx = current_cx("mpsc::UnboundedReceiver::recv");
+        recv_result(self.inner.recv(&cx).await)
+    }
+
+    /// Receives a message if one is queued.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        try_recv_result(self.inner.try_recv())
+    }
+
+    /// Closes the channel to new messages; queued ones can still be received.
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+}
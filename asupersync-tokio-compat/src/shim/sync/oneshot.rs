@@ -0,0 +1,97 @@
+//! Single-value channels, like `tokio::sync::oneshot`.
+//!
+//! The [`Receiver`] is itself a future, as in Tokio. Because it owns its
+//! Asupersync receive future, the value type must be `Send + 'static`.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use asupersync::channel::oneshot as inner;
+use asupersync::channel::oneshot::{RecvError as InnerRecvError, SendError};
+
+use self::error::RecvError;
+use crate::shim::{Divergence, current_cx, warn_divergence};
+
+/// Channel error types, like `tokio::sync::oneshot::error`.
+pub mod error {
+    use std::fmt;
+
+    /// The sender was dropped without sending, or the receiving task was
+    /// cancelled.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct RecvError(pub(super) ());
+
+    impl fmt::Display for RecvError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("channel closed")
+        }
+    }
+
+    impl std::error::Error for RecvError {}
+}
+
+type RecvFuture<T> = Pin<Box<dyn Future<Output = Result<T, InnerRecvError>> + Send>>;
+
+/// Creates a oneshot channel.
+pub fn channel<T: Send + 'static>() -> (Sender<T>, Receiver<T>) {
+    let (tx, mut rx) = inner::channel();
+    let recv = Box::pin(async move {
+        let cx = current_cx("oneshot::Receiver");
+        rx.recv(&cx).await
+    });
+    (Sender { inner: tx }, Receiver { recv })
+}
+
+/// Sends the single value.
+#[derive(Debug)]
+pub struct Sender<T> {
+    inner: inner::Sender<T>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `value`, handing it back if the receiver is gone.
+    pub fn send(self, value: T) -> Result<(), T> {
+        self.inner
+            .send_blocking(value)
+            .map_err(|(SendError::Disconnected(value) | SendError::Cancelled(value))| value)
+    }
+
+    /// Returns `true` once the receiver is gone.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+/// Receives the single value; await it directly.
+///
+/// # Panics
+///
+/// Panics when polled outside an Asupersync task.
+pub struct Receiver<T> {
+    recv: RecvFuture<T>,
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.recv.as_mut().poll(cx).map(|result| match result {
+            Ok(value) => Ok(value),
+            Err(InnerRecvError::Cancelled) => {
+                warn_divergence(Divergence::CancelledWait);
+                Err(RecvError(()))
+            }
+            Err(InnerRecvError::Closed | InnerRecvError::PolledAfterCompletion) => {
+                Err(RecvError(()))
+            }
+        })
+    }
+}
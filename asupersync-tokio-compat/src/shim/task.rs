@@ -0,0 +1,374 @@
+//! `tokio::task` equivalents: [`spawn`], [`JoinHandle`], [`JoinError`].
+//!
+//! # Where spawned tasks live
+//!
+//! Tokio tasks are detached from their spawner; dropping the `JoinHandle`
+//! leaves the task running with no owner. The shim keeps that API shape but
+//! never orphans work: each task is spawned into the *compat region*, an
+//! ambient region owned by the runtime.
+//!
+//! - On the native runtime ([`Runtime::block_on`] and worker threads) the
+//!   compat region is the runtime's root region, which is cancelled and
+//!   drained when the runtime shuts down.
+//! - Under the lab runtime, which has no ambient runtime handle, the compat
+//!   region is the spawning task's own region, so region close waits for it.
+//!
+//! # Cancellation
+//!
+//! Tokio cancels a task by dropping its future at the next `.await`. The shim
+//! emulates that on top of the cancel protocol: once the task's `Cx` observes
+//! cancellation (region close, runtime shutdown) or [`JoinHandle::abort`] is
+//! called, the task's future is dropped at its next poll and the handle
+//! resolves to a cancelled [`JoinError`]. Panics are caught and reported as
+//! [`JoinError::is_panic`], as in Tokio.
+
+use std::fmt;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use asupersync::Cx;
+use asupersync::runtime::{Runtime, SpawnError};
+use pin_project_lite::pin_project;
+use tokio::sync::oneshot;
+
+use super::{Divergence, warn_divergence};
+use crate::blocking::panic_message;
+
+pub use asupersync::runtime::yield_now;
+
+/// Abort and completion state shared between a task and its handles.
+#[derive(Debug, Default)]
+struct TaskControl {
+    aborted: AtomicBool,
+    finished: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl TaskControl {
+    fn abort(&self) {
+        self.aborted.store(true, Ordering::Release);
+        let waker = self
+            .waker
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::Acquire)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut slot = self
+            .waker
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if !slot.as_ref().is_some_and(|current| current.will_wake(waker)) {
+            *slot = Some(waker.clone());
+        }
+    }
+}
+
+pin_project! {
+    /// Drops the wrapped future at its next poll once cancellation or abort
+    /// is requested, and catches its panics.
+    struct Supervised<F> {
+        #[pin]
+        future: F,
+        cx: Cx,
+        control: Arc<TaskControl>,
+    }
+}
+
+impl<F: Future> Future for Supervised<F> {
+    type Output = Option<std::thread::Result<F::Output>>;
+
+    fn poll(self: Pin<&mut Self>, poll_cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        // Register before checking so an abort between the two still wakes us.
+        this.control.register(poll_cx.waker());
+        if this.control.is_aborted() || this.cx.is_cancel_requested() {
+            return Poll::Ready(None);
+        }
+        let future = this.future;
+        match catch_unwind(AssertUnwindSafe(|| future.poll(poll_cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Some(Ok(output))),
+            Err(payload) => Poll::Ready(Some(Err(payload))),
+        }
+    }
+}
+
+async fn run_task<F: Future>(
+    future: F,
+    cx: Cx,
+    control: Arc<TaskControl>,
+    result: oneshot::Sender<Result<F::Output, JoinError>>,
+) {
+    let outcome = Supervised {
+        future,
+        cx,
+        control: Arc::clone(&control),
+    }
+    .await;
+    let outcome = match outcome {
+        Some(Ok(output)) => Ok(output),
+        Some(Err(payload)) => Err(JoinError::panic(panic_message(&payload))),
+        None => Err(JoinError::cancelled()),
+    };
+    control.finished.store(true, Ordering::Release);
+    let _ = result.send(outcome);
+}
+
+/// Spawns a task into the compat region, like `tokio::spawn`.
+///
+/// See the [module docs](self) for where the task lives and how it is
+/// cancelled. Dropping the returned handle detaches from the task without
+/// cancelling it; the owning region still waits for it.
+///
+/// # Panics
+///
+/// Panics when called outside an Asupersync runtime (no runtime handle and
+/// no current `Cx`), or when the owning region refuses the spawn, mirroring
+/// `tokio::spawn` outside a Tokio runtime.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let control = Arc::new(TaskControl::default());
+    let (sender, result) = oneshot::channel();
+    let task = {
+        let control = Arc::clone(&control);
+        move |cx: Cx| run_task(future, cx, control, sender)
+    };
+    let spawned = if let Some(handle) = Runtime::current_handle() {
+        handle.try_spawn_with_cx(task)
+    } else if let Some(cx) = Cx::current() {
+        cx.spawn(task).map(drop)
+    } else {
+        Err(SpawnError::RuntimeUnavailable)
+    };
+    if let Err(err) = spawned {
+        panic!("tokio shim `spawn` must be called from within an asupersync runtime: {err}");
+    }
+    JoinHandle { result, control }
+}
+
+/// An owned permission to join a task spawned with [`spawn`].
+///
+/// Awaiting the handle yields the task's output, or a [`JoinError`] if it
+/// was cancelled or panicked.
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    result: oneshot::Receiver<Result<T, JoinError>>,
+    control: Arc<TaskControl>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Requests cancellation of the task.
+    ///
+    /// Unlike Tokio, this goes through the cancel protocol: the task's future
+    /// is dropped at its next poll rather than synchronously, and a task that
+    /// completes before that poll still returns its output.
+    pub fn abort(&self) {
+        warn_divergence(Divergence::Abort);
+        self.control.abort();
+    }
+
+    /// Returns `true` once the task has completed, been cancelled, or panicked.
+    pub fn is_finished(&self) -> bool {
+        self.control.is_finished()
+    }
+
+    /// Returns a handle that can abort the task without owning the result.
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle {
+            control: Arc::clone(&self.control),
+        }
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.result)
+            .poll(cx)
+            .map(|result| result.unwrap_or_else(|_| Err(JoinError::cancelled())))
+    }
+}
+
+/// A cloneable handle for aborting a task, like `tokio::task::AbortHandle`.
+#[derive(Debug, Clone)]
+pub struct AbortHandle {
+    control: Arc<TaskControl>,
+}
+
+impl AbortHandle {
+    /// Requests cancellation of the task. See [`JoinHandle::abort`].
+    pub fn abort(&self) {
+        warn_divergence(Divergence::Abort);
+        self.control.abort();
+    }
+
+    /// Returns `true` once the task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.control.is_finished()
+    }
+}
+
+#[derive(Debug)]
+enum Repr {
+    Cancelled,
+    Panic(String),
+}
+
+/// Task failed to run to completion.
+#[derive(Debug)]
+pub struct JoinError {
+    repr: Repr,
+}
+
+impl JoinError {
+    const fn cancelled() -> Self {
+        Self {
+            repr: Repr::Cancelled,
+        }
+    }
+
+    const fn panic(message: String) -> Self {
+        Self {
+            repr: Repr::Panic(message),
+        }
+    }
+
+    /// Returns `true` if the task was cancelled or aborted.
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self.repr, Repr::Cancelled)
+    }
+
+    /// Returns `true` if the task panicked.
+    pub const fn is_panic(&self) -> bool {
+        matches!(self.repr, Repr::Panic(_))
+    }
+
+    /// Returns the panic message, if the task panicked.
+    ///
+    /// Tokio hands back the panic payload instead; the shim keeps only the
+    /// message.
+    pub fn panic_message(&self) -> Option<&str> {
+        match &self.repr {
+            Repr::Panic(message) => Some(message),
+            Repr::Cancelled => None,
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.repr {
+            Repr::Cancelled => write!(f, "task was cancelled"),
+            Repr::Panic(message) => write!(f, "task panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asupersync::lab::{LabConfig, LabRuntime};
+    use asupersync::types::Budget;
+    use std::time::Duration;
+
+    fn run_in_lab<F>(future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut lab = LabRuntime::new(LabConfig::new(7).with_auto_advance());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (task, mut handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, future)
+            .expect("create lab task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_with_auto_advance();
+        handle
+            .try_join()
+            .expect("lab task joined")
+            .expect("lab task finished");
+        assert!(lab.is_quiescent(), "spawned tasks drained with the region");
+    }
+
+    #[test]
+    fn test_spawn_join_output_and_panic() {
+        run_in_lab(async {
+            let handle = spawn(async { 40 + 2 });
+            assert_eq!(handle.await.expect("joined"), 42);
+
+            let handle = spawn(async { panic!("boom") });
+            let err = handle.await.expect_err("panicked");
+            assert!(err.is_panic());
+            assert_eq!(err.panic_message(), Some("boom"));
+            assert_eq!(err.to_string(), "task panicked: boom");
+        });
+    }
+
+    #[test]
+    fn test_abort_drops_future_at_next_poll() {
+        run_in_lab(async {
+            let dropped = Arc::new(AtomicBool::new(false));
+            let guard = DropFlag(Arc::clone(&dropped));
+            let handle = spawn(async move {
+                let _guard = guard;
+                crate::shim::time::sleep(Duration::from_secs(3600)).await;
+            });
+            yield_now().await;
+            handle.abort();
+            let err = handle.await.expect_err("aborted");
+            assert!(err.is_cancelled());
+            assert!(dropped.load(Ordering::Acquire));
+            assert!(crate::shim::reported_divergences().contains(&Divergence::Abort));
+        });
+    }
+
+    #[test]
+    fn test_detached_task_is_not_orphaned() {
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        run_in_lab(async move {
+            drop(spawn(async move {
+                crate::shim::time::sleep(Duration::from_millis(5)).await;
+                flag.store(true, Ordering::Release);
+            }));
+        });
+        assert!(ran.load(Ordering::Acquire));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be called from within an asupersync runtime")]
+    fn test_spawn_outside_runtime_panics() {
+        drop(spawn(async {}));
+    }
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+}
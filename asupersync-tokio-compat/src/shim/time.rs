@@ -0,0 +1,101 @@
+//! `tokio::time` equivalents on Asupersync's timer.
+//!
+//! Deadlines are measured on the ambient clock: the runtime's timer driver
+//! when called from a task (virtual time under the lab runtime), the wall
+//! clock otherwise. Instants are Asupersync [`Time`] values rather than
+//! `tokio::time::Instant`.
+
+use std::future::Future;
+
+pub use asupersync::time::{MissedTickBehavior, Sleep, TimeoutFuture};
+pub use asupersync::types::Time;
+pub use std::time::Duration;
+
+use asupersync::time::wall_now;
+
+/// Error types, like `tokio::time::error`.
+pub mod error {
+    pub use asupersync::time::Elapsed;
+}
+
+/// Waits until `duration` has elapsed, like `tokio::time::sleep`.
+pub fn sleep(duration: Duration) -> Sleep {
+    asupersync::time::sleep(wall_now(), duration)
+}
+
+/// Waits until `deadline`, like `tokio::time::sleep_until`.
+pub fn sleep_until(deadline: Time) -> Sleep {
+    asupersync::time::sleep_until(deadline)
+}
+
+/// Requires `future` to complete within `duration`, like
+/// `tokio::time::timeout`.
+///
+/// The deadline is also clamped to the calling task's own deadline, so a
+/// timeout can fire earlier than requested but never later.
+pub fn timeout<F: Future>(duration: Duration, future: F) -> TimeoutFuture<F> {
+    asupersync::time::timeout(wall_now(), duration, future)
+}
+
+/// Creates an [`Interval`] whose first tick completes immediately, like
+/// `tokio::time::interval`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, as Tokio does.
+pub fn interval(period: Duration) -> Interval {
+    interval_at(wall_now(), period)
+}
+
+/// Creates an [`Interval`] whose first tick completes at `start`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, as Tokio does.
+pub fn interval_at(start: Time, period: Duration) -> Interval {
+    assert!(!period.is_zero(), "`period` must be non-zero.");
+    Interval {
+        inner: asupersync::time::interval_at(start, period),
+    }
+}
+
+/// Ticks at a fixed period, like `tokio::time::Interval`.
+#[derive(Debug)]
+pub struct Interval {
+    inner: asupersync::time::Interval,
+}
+
+impl Interval {
+    /// Completes at the next tick and returns its scheduled time.
+    ///
+    /// Cancel safe: dropping the future does not consume a tick.
+    pub async fn tick(&mut self) -> Time {
+        loop {
+            let now = wall_now();
+            if let Some(tick) = self.inner.poll_tick(now) {
+                return tick;
+            }
+            asupersync::time::sleep(now, self.inner.remaining(now)).await;
+        }
+    }
+
+    /// Returns the period between ticks.
+    pub const fn period(&self) -> Duration {
+        self.inner.period()
+    }
+
+    /// Returns how missed ticks are handled.
+    pub const fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.inner.missed_tick_behavior()
+    }
+
+    /// Sets how missed ticks are handled.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.inner.set_missed_tick_behavior(behavior);
+    }
+
+    /// Resets the interval so the next tick is one period from now.
+    pub fn reset(&mut self) {
+        self.inner.reset_after(wall_now(), self.inner.period());
+    }
+}
@@ -0,0 +1,193 @@
+//! A small Tokio program ported by swapping the dependency for the shim.
+//!
+//! Everything in `app` is written against the Tokio API; the only change
+//! from the original is the `use ... as tokio` alias. Each scenario runs on
+//! the native multi-threaded runtime and under the deterministic lab
+//! runtime. TCP is exercised on the native runtime only, since the lab
+//! runtime has no network reactor.
+
+use std::future::Future;
+
+use asupersync::lab::{LabConfig, LabRuntime};
+use asupersync::runtime::RuntimeBuilder;
+use asupersync::types::Budget;
+
+mod app {
+    use asupersync_tokio_compat::shim as tokio;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::{Mutex, RwLock, Semaphore, mpsc, oneshot};
+
+    struct Job {
+        n: u64,
+        reply: oneshot::Sender<u64>,
+    }
+
+    /// Squares `inputs` on a pool of three workers, at most two at a time.
+    pub async fn square_all(inputs: Vec<u64>) -> Vec<u64> {
+        let (tx, rx) = mpsc::channel::<Job>(4);
+        let rx = Arc::new(Mutex::new(rx));
+        let limit = Arc::new(Semaphore::new(2));
+        let processed = Arc::new(RwLock::new(0_usize));
+
+        let mut workers = Vec::new();
+        for _ in 0..3 {
+            let rx = Arc::clone(&rx);
+            let limit = Arc::clone(&limit);
+            let processed = Arc::clone(&processed);
+            workers.push(tokio::spawn(async move {
+                loop {
+                    let job = rx.lock().await.recv().await;
+                    let Some(job) = job else { break };
+                    let _permit = limit.acquire().await.expect("semaphore open");
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    *processed.write().await += 1;
+                    let _ = job.reply.send(job.n * job.n);
+                }
+            }));
+        }
+
+        let mut replies = Vec::new();
+        for n in inputs {
+            let (reply, response) = oneshot::channel();
+            tx.send(Job { n, reply }).await.expect("workers running");
+            replies.push(response);
+        }
+        drop(tx);
+
+        let mut squares = Vec::new();
+        for response in replies {
+            squares.push(response.await.expect("worker replied"));
+        }
+        for worker in workers {
+            worker.await.expect("worker exited cleanly");
+        }
+        assert_eq!(*processed.read().await, squares.len());
+        squares
+    }
+
+    /// Returns `true` if a slow call is cut off by its timeout.
+    pub async fn slow_call_times_out() -> bool {
+        let slow = tokio::time::sleep(Duration::from_secs(60));
+        tokio::time::timeout(Duration::from_millis(10), slow)
+            .await
+            .is_err()
+    }
+
+    /// Waits for `ticks` ticks of a 2ms interval.
+    pub async fn count_ticks(ticks: usize) -> usize {
+        let mut interval = tokio::time::interval(Duration::from_millis(2));
+        let mut seen = 0;
+        for _ in 0..ticks {
+            interval.tick().await;
+            seen += 1;
+        }
+        seen
+    }
+
+    /// Returns `true` if aborting a long-running task cancels it.
+    pub async fn abort_long_task() -> bool {
+        let handle = tokio::spawn(async {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        });
+        tokio::task::yield_now().await;
+        handle.abort();
+        handle.await.expect_err("task aborted").is_cancelled()
+    }
+
+    /// Sends `message` through a one-shot TCP echo server.
+    pub async fn echo(message: &'static [u8]) -> std::io::Result<Vec<u8>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await?;
+            let mut buf = [0_u8; 64];
+            let n = socket.read(&mut buf).await?;
+            socket.write_all(&buf[..n]).await?;
+            Ok::<_, std::io::Error>(())
+        });
+
+        let mut client = TcpStream::connect(addr).await?;
+        client.write_all(message).await?;
+        let mut reply = vec![0; message.len()];
+        client.read_exact(&mut reply).await?;
+        server.await.expect("server task")?;
+        Ok(reply)
+    }
+}
+
+fn on_native<F>(future: F) -> F::Output
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let runtime = RuntimeBuilder::new()
+        .worker_threads(2)
+        .build()
+        .expect("build runtime");
+    runtime.block_on(runtime.handle().spawn(future))
+}
+
+fn in_lab<F>(future: F) -> F::Output
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let mut lab = LabRuntime::new(LabConfig::new(42).with_auto_advance());
+    let root = lab.state.create_root_region(Budget::INFINITE);
+    let (task, mut handle) = lab
+        .state
+        .create_task(root, Budget::INFINITE, future)
+        .expect("create lab task");
+    lab.scheduler.lock().schedule(task, 0);
+    lab.run_with_auto_advance();
+    let violations = lab.check_invariants();
+    assert!(violations.is_empty(), "invariant violations: {violations:?}");
+    assert!(lab.is_quiescent(), "lab runtime quiescent");
+    handle
+        .try_join()
+        .expect("lab task joined")
+        .expect("lab task finished")
+}
+
+#[test]
+fn worker_pool_native() {
+    assert_eq!(on_native(app::square_all(vec![1, 2, 3, 4, 5])), vec![1, 4, 9, 16, 25]);
+}
+
+#[test]
+fn worker_pool_lab() {
+    assert_eq!(in_lab(app::square_all(vec![1, 2, 3, 4, 5])), vec![1, 4, 9, 16, 25]);
+}
+
+#[test]
+fn timers_native() {
+    assert!(on_native(app::slow_call_times_out()));
+    assert_eq!(on_native(app::count_ticks(3)), 3);
+}
+
+#[test]
+fn timers_lab() {
+    assert!(in_lab(app::slow_call_times_out()));
+    assert_eq!(in_lab(app::count_ticks(3)), 3);
+}
+
+#[test]
+fn abort_native() {
+    assert!(on_native(app::abort_long_task()));
+}
+
+#[test]
+fn abort_lab() {
+    assert!(in_lab(app::abort_long_task()));
+}
+
+#[test]
+fn tcp_echo_native() {
+    let reply = on_native(app::echo(b"hello shim")).expect("echo round trip");
+    assert_eq!(reply, b"hello shim");
+}
@@ -0,0 +1,56 @@
+//! Receiver cost of batched (Merkle root) versus per-symbol authentication.
+//!
+//! Each iteration verifies one 256-symbol burst. Per-symbol framing pays an
+//! HMAC per symbol; batched framing pays one MAC per 32-symbol batch plus a
+//! leaf hash and, amortized, one interior hash per symbol.
+
+#![allow(missing_docs)]
+
+use asupersync::security::{
+    AuthFrame, AuthFraming, BatchConfig, SecurityContext, SymbolSigner, SymbolVerifier,
+};
+use asupersync::types::{Symbol, Time};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const BURST: u32 = 256;
+const SYMBOL_SIZES: [usize; 3] = [64, 256, 1024];
+
+fn wire_frames(framing: AuthFraming, symbol_size: usize) -> Vec<Vec<u8>> {
+    let config = BatchConfig::default().with_max_symbols(32);
+    let mut signer = SymbolSigner::new(SecurityContext::for_testing(7), framing, config);
+    let mut frames: Vec<AuthFrame> = Vec::new();
+    for esi in 0..BURST {
+        let symbol = Symbol::new_for_test(1, 0, esi, &vec![esi as u8; symbol_size]);
+        frames.extend(signer.push(symbol, Time::ZERO));
+    }
+    frames.extend(signer.flush());
+    frames.iter().map(AuthFrame::encode).collect()
+}
+
+fn bench_verify_burst(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_auth/verify_burst");
+    group.throughput(Throughput::Elements(u64::from(BURST)));
+    for symbol_size in SYMBOL_SIZES {
+        for framing in AuthFraming::ALL {
+            let wire = wire_frames(framing, symbol_size);
+            let name = match framing {
+                AuthFraming::PerSymbol => "per_symbol",
+                AuthFraming::MerkleBatch => "merkle_batch",
+            };
+            group.bench_with_input(BenchmarkId::new(name, symbol_size), &wire, |b, wire| {
+                b.iter(|| {
+                    let ctx = SecurityContext::for_testing(7);
+                    let mut verifier = SymbolVerifier::new(ctx, framing);
+                    for frame in wire {
+                        black_box(verifier.verify_bytes(frame).expect("frame verifies"));
+                    }
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify_burst);
+criterion_main!(benches);
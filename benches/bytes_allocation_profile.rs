@@ -265,11 +265,57 @@ fn bench_mixed_allocation_patterns(c: &mut Criterion) {
     group.finish();
 }
 
+/// Scenario E: Small-frame protocol parsing
+///
+/// Parses a stream of length-prefixed frames with payloads at the inline
+/// capacity (no allocation per frame) and just past it (heap head plus shared
+/// backing per frame). The allocation counts themselves are asserted in
+/// `tests/allocation_audit.rs`; this measures what the difference costs.
+fn bench_small_frame_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_frame_parsing");
+
+    const FRAMES: usize = 256;
+    let payload_sizes = [
+        ("inline", BytesMut::INLINE_CAPACITY),
+        ("heap", Bytes::INLINE_CAPACITY + 1),
+    ];
+
+    for (representation, payload_len) in payload_sizes {
+        let mut wire = BytesMut::with_capacity(FRAMES * (payload_len + 2));
+        for i in 0..FRAMES {
+            wire.put_slice(&[0x01, payload_len as u8]);
+            wire.resize(wire.len() + payload_len, i as u8);
+        }
+        let wire = wire.freeze();
+
+        group.throughput(Throughput::Elements(FRAMES as u64));
+        group.bench_with_input(
+            BenchmarkId::new(representation, payload_len),
+            &wire,
+            |b, wire| {
+                b.iter(|| {
+                    let mut buf = BytesMut::from(&wire[..]);
+                    let mut frames = Vec::with_capacity(FRAMES);
+                    while !buf.is_empty() {
+                        let header = buf.split_to(2);
+                        let payload = buf.split_to(usize::from(header[1])).freeze();
+                        frames.push(black_box(payload));
+                    }
+                    black_box(frames)
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_bytes_mut_incremental_growth,
     bench_bytes_mut_splitting,
     bench_bytes_creation,
-    bench_mixed_allocation_patterns
+    bench_mixed_allocation_patterns,
+    bench_small_frame_parsing
 );
 criterion_main!(benches);
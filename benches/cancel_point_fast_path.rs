@@ -0,0 +1,49 @@
+//! Cost of cancellation points on the happy path.
+//!
+//! `cancel_point` and `checkpoint_every` read the task's cached cancel flag
+//! with a single relaxed load while cancellation is not requested, so both
+//! should stay within noise of an empty loop body. `checkpoint` is the
+//! locking baseline they replace in inner loops. The `raised` cases measure
+//! the slow path taken once a request arrives.
+
+use asupersync::Cx;
+use asupersync::types::CancelKind;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn bench_happy_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cancel_point_happy_path");
+    let cx = Cx::for_testing();
+
+    group.bench_function("cancel_point", |b| {
+        b.iter(|| black_box(cx.cancel_point()));
+    });
+
+    for interval in [1_u32, 64, 256] {
+        group.bench_function(BenchmarkId::new("checkpoint_every", interval), |b| {
+            let mut points = cx.checkpoint_every(interval);
+            b.iter(|| black_box(points.check()));
+        });
+    }
+
+    group.bench_function("checkpoint", |b| {
+        b.iter(|| black_box(cx.checkpoint().is_ok()));
+    });
+
+    group.finish();
+}
+
+fn bench_raised(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cancel_point_raised");
+    let cx = Cx::for_testing();
+    cx.cancel_with(CancelKind::User, Some("bench"));
+
+    group.bench_function("cancel_point", |b| {
+        b.iter(|| black_box(cx.cancel_point()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_happy_path, bench_raised);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! Capability attenuation overhead benchmarks.
+//!
+//! Guards the claim that an unrestricted `Cx` pays nothing for attenuation:
+//! `check_capability` on an unrestricted context should stay within noise of
+//! an empty call, and cloning an unrestricted context should not move. The
+//! restricted rows show the cost once a restriction chain is present.
+
+#![cfg(feature = "test-internals")]
+#![allow(missing_docs)]
+
+use asupersync::Cx;
+use asupersync::cx::{Capability, CapabilitySet};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn bench_check_capability(c: &mut Criterion) {
+    let unrestricted = Cx::for_testing();
+    let restricted = unrestricted
+        .restrict_capabilities(CapabilitySet::all().without(Capability::Process), "outer")
+        .and_then(|cx| {
+            cx.restrict_capabilities(
+                CapabilitySet::all()
+                    .without(Capability::Process)
+                    .without(Capability::Fs),
+                "inner",
+            )
+        })
+        .expect("narrowing succeeds");
+
+    let mut group = c.benchmark_group("capability_attenuation/check");
+    group.bench_function("unrestricted", |b| {
+        b.iter(|| black_box(&unrestricted).check_capability(black_box(Capability::Net)));
+    });
+    group.bench_function("restricted_allowed", |b| {
+        b.iter(|| black_box(&restricted).check_capability(black_box(Capability::Net)));
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("capability_attenuation/clone");
+    group.bench_function("unrestricted", |b| {
+        b.iter(|| black_box(&unrestricted).clone());
+    });
+    group.bench_function("restricted", |b| {
+        b.iter(|| black_box(&restricted).clone());
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_check_capability);
+criterion_main!(benches);
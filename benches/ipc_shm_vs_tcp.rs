@@ -0,0 +1,132 @@
+//! Shared-memory IPC channel versus loopback TCP throughput benchmark.
+//!
+//! Moves a batch of fixed-size frames from a producer thread to the
+//! benchmark thread, once through `channel::ipc` (one copy into the shared
+//! ring, then read in place through `recv_ref`) and once through a
+//! blocking loopback TCP connection (kernel copies on both sides). Both
+//! transports live for the whole benchmark so only steady-state transfer is
+//! measured.
+//!
+//! Gated behind `criterion-benches`; run with
+//! `cargo bench --bench ipc_shm_vs_tcp --features test-internals,criterion-benches`.
+
+#![allow(missing_docs)]
+
+use asupersync::Cx;
+use asupersync::channel::ipc::{self, IpcConfig, Receiver, Sender};
+use asupersync::net::unix::UnixStream;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures_lite::future::block_on;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+const BATCH: usize = 256;
+const FRAME_SIZES: [usize; 3] = [256, 4 * 1024, 64 * 1024];
+
+struct IpcScenario {
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+    frame: Vec<u8>,
+}
+
+impl IpcScenario {
+    fn new(frame_size: usize) -> Self {
+        let (a, b) = std::os::unix::net::UnixStream::pair().expect("socket pair");
+        let a = UnixStream::from_std(a).expect("nonblocking stream");
+        let b = UnixStream::from_std(b).expect("nonblocking stream");
+        let config = IpcConfig::default().with_slot_size(frame_size);
+        let (tx, rx) = thread::scope(|scope| {
+            let tx = scope.spawn(|| block_on(ipc::sender::<Vec<u8>>(a, config)).expect("sender"));
+            let rx = block_on(ipc::receiver::<Vec<u8>>(b)).expect("receiver");
+            (tx.join().expect("sender handshake"), rx)
+        });
+        Self {
+            tx,
+            rx,
+            frame: vec![0x5a; frame_size],
+        }
+    }
+
+    fn run_iteration(&mut self) {
+        let Self { tx, rx, frame } = self;
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                let cx = Cx::for_testing();
+                for _ in 0..BATCH {
+                    block_on(tx.send(&cx, frame.clone())).expect("ipc send");
+                }
+            });
+            let cx = Cx::for_testing();
+            for _ in 0..BATCH {
+                let frame = block_on(rx.recv_ref(&cx)).expect("ipc recv");
+                std::hint::black_box(frame.bytes().iter().fold(0u8, u8::wrapping_add));
+                frame.release();
+            }
+        });
+    }
+}
+
+struct TcpScenario {
+    client: TcpStream,
+    server: TcpStream,
+    frame: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl TcpScenario {
+    fn new(frame_size: usize) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        let addr = listener.local_addr().expect("listener addr");
+        let client = TcpStream::connect(addr).expect("connect");
+        let (server, _) = listener.accept().expect("accept");
+        client.set_nodelay(true).expect("client nodelay");
+        Self {
+            client,
+            server,
+            frame: vec![0x5a; frame_size],
+            buf: vec![0; frame_size],
+        }
+    }
+
+    fn run_iteration(&mut self) {
+        let Self {
+            client,
+            server,
+            frame,
+            buf,
+        } = self;
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..BATCH {
+                    client.write_all(frame).expect("tcp write");
+                }
+            });
+            for _ in 0..BATCH {
+                server.read_exact(buf).expect("tcp read");
+                std::hint::black_box(&buf);
+            }
+        });
+    }
+}
+
+fn bench_ipc_shm_vs_tcp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ipc_shm_vs_tcp");
+    for frame_size in FRAME_SIZES {
+        group.throughput(Throughput::Bytes((BATCH * frame_size) as u64));
+
+        let mut ipc = IpcScenario::new(frame_size);
+        group.bench_with_input(BenchmarkId::new("ipc_shm", frame_size), &frame_size, |b, _| {
+            b.iter(|| ipc.run_iteration());
+        });
+
+        let mut tcp = TcpScenario::new(frame_size);
+        group.bench_with_input(BenchmarkId::new("tcp_loopback", frame_size), &frame_size, |b, _| {
+            b.iter(|| tcp.run_iteration());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ipc_shm_vs_tcp);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+//! Write and read throughput of the embedded key-value store.
+//!
+//! Commits run against the simulated filesystem, which isolates the
+//! store's own costs (encoding, checksums, index updates, scheduler
+//! admission), and against a temporary directory on the real disk. On real
+//! disks `SyncPolicy::Always` is bounded by fsync latency, typically a few
+//! hundred to a few thousand commits per second; batching many writes per
+//! commit or `SyncPolicy::Interval` recovers throughput at the cost of the
+//! durability window. Against the simulated filesystem a single-key commit
+//! is expected to stay in the low microseconds, and reads are one index
+//! lookup plus one positioned read.
+
+use asupersync::cx::Cx;
+use asupersync::fs::UnixVfs;
+use asupersync::lab::SimFs;
+use asupersync::store::{KvStore, KvStoreConfig, SyncPolicy, WriteBatch};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures_lite::future::block_on;
+use std::hint::black_box;
+
+const VALUE_BYTES: usize = 128;
+const KEYS: usize = 1024;
+
+fn key(index: usize) -> Vec<u8> {
+    format!("key/{index:08}").into_bytes()
+}
+
+fn config(sync: SyncPolicy) -> KvStoreConfig {
+    KvStoreConfig {
+        sync,
+        ..KvStoreConfig::default()
+    }
+}
+
+fn bench_sim_writes(c: &mut Criterion) {
+    let cx = Cx::for_testing();
+    let value = vec![0x5a; VALUE_BYTES];
+    let mut group = c.benchmark_group("kv_store_sim_write");
+
+    for (label, sync) in [("always", SyncPolicy::Always), ("never", SyncPolicy::Never)] {
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("put", label), |b| {
+            let store = block_on(KvStore::open(SimFs::default(), "/kv", config(sync)))
+                .expect("open store");
+            let mut next = 0;
+            b.iter(|| {
+                next = (next + 1) % KEYS;
+                block_on(store.put(&cx, key(next), value.clone())).expect("put");
+            });
+        });
+
+        for batch_size in [16, 256] {
+            group.throughput(Throughput::Elements(batch_size as u64));
+            group.bench_function(BenchmarkId::new(format!("batch_{label}"), batch_size), |b| {
+                let store = block_on(KvStore::open(SimFs::default(), "/kv", config(sync)))
+                    .expect("open store");
+                b.iter(|| {
+                    let batch = (0..batch_size).fold(WriteBatch::new(), |batch, index| {
+                        batch.put(key(index), value.clone())
+                    });
+                    block_on(store.batch(&cx, batch)).expect("batch");
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_sim_reads(c: &mut Criterion) {
+    let cx = Cx::for_testing();
+    let store = block_on(KvStore::open(SimFs::default(), "/kv", config(SyncPolicy::Never)))
+        .expect("open store");
+    block_on(async {
+        for index in 0..KEYS {
+            store.put(&cx, key(index), vec![0x5a; VALUE_BYTES]).await.expect("put");
+        }
+    });
+
+    let mut group = c.benchmark_group("kv_store_sim_read");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("get", |b| {
+        let mut next = 0;
+        b.iter(|| {
+            next = (next + 7) % KEYS;
+            black_box(block_on(store.get(&cx, &key(next))).expect("get"));
+        });
+    });
+    group.throughput(Throughput::Elements(KEYS as u64));
+    group.bench_function("range_all", |b| {
+        b.iter(|| black_box(block_on(store.range(&cx, ..)).expect("range")));
+    });
+    group.finish();
+}
+
+fn bench_disk_writes(c: &mut Criterion) {
+    let cx = Cx::for_testing();
+    let value = vec![0x5a; VALUE_BYTES];
+    let mut group = c.benchmark_group("kv_store_disk_write");
+    group.throughput(Throughput::Elements(1));
+
+    for (label, sync) in [("always", SyncPolicy::Always), ("never", SyncPolicy::Never)] {
+        group.bench_function(BenchmarkId::new("put", label), |b| {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let store = block_on(KvStore::open(UnixVfs::new(), dir.path(), config(sync)))
+                .expect("open store");
+            let mut next = 0;
+            b.iter(|| {
+                next = (next + 1) % KEYS;
+                block_on(store.put(&cx, key(next), value.clone())).expect("put");
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_sim_writes, bench_sim_reads, bench_disk_writes);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Cost of the log collector's emit path with and without log-control
+//! overrides.
+//!
+//! With no override in force the collector checks its base level exactly as
+//! it did before runtime log control existed, so the `no_override` cases are
+//! the regression guard: they should match a collector that never had a
+//! control, to within noise. The `override` cases measure the epoch-cached
+//! lookup taken while an override is active.
+
+use asupersync::observability::{LogChange, LogCollector, LogEntry, LogLevel, LogScope};
+use asupersync::types::Time;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn collector() -> LogCollector {
+    LogCollector::new(1024).with_min_level(LogLevel::Info)
+}
+
+fn bench_emit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("log_control_emit");
+    let now = Time::from_millis(1);
+
+    for (label, level) in [("filtered", LogLevel::Debug), ("recorded", LogLevel::Info)] {
+        let entry = || LogEntry::new(level, "db.query").with_timestamp(now);
+
+        group.bench_function(BenchmarkId::new("no_override", label), |b| {
+            let collector = collector();
+            b.iter(|| collector.log(black_box(entry())));
+        });
+
+        group.bench_function(BenchmarkId::new("override", label), |b| {
+            let collector = collector();
+            collector
+                .control()
+                .apply(
+                    "bench",
+                    &[LogChange::level(LogScope::Event("http.".into()), LogLevel::Trace)],
+                    Time::ZERO,
+                )
+                .expect("apply override");
+            b.iter(|| collector.log(black_box(entry())));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_emit);
+criterion_main!(benches);
@@ -0,0 +1,147 @@
+//! Multi-reactor echo benchmark.
+//!
+//! Runs a multi-listener TCP echo workload on runtimes configured with one,
+//! two, and four I/O reactors. Each listener is pinned to a reactor with
+//! `TcpSocket::set_reactor_affinity`, so accepted connections share their
+//! listener's reactor thread. One iteration drives every client connection
+//! through a fixed number of ping-pong rounds concurrently.
+//!
+//! After each runtime size the per-reactor metrics are printed, showing how
+//! dispatch was spread across reactors.
+//!
+//! Gated behind `criterion-benches`; run with
+//! `cargo bench --bench multi_reactor_echo --features criterion-benches`.
+
+#![allow(missing_docs)]
+
+use asupersync::io::{AsyncReadExt, AsyncWriteExt};
+use asupersync::net::{TcpSocket, TcpStream};
+use asupersync::runtime::builder::{Runtime, RuntimeBuilder};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+const WORKERS: usize = 4;
+const LISTENERS: usize = 4;
+const CONNECTIONS_PER_LISTENER: usize = 8;
+const ROUNDS: usize = 16;
+const MESSAGE: [u8; 64] = [0x5a; 64];
+
+struct EchoScenario {
+    runtime: Runtime,
+    clients: Vec<TcpStream>,
+}
+
+impl EchoScenario {
+    fn new(reactors: usize) -> Self {
+        let runtime = RuntimeBuilder::new()
+            .worker_threads(WORKERS)
+            .reactors(reactors)
+            .build()
+            .expect("build benchmark runtime");
+        let handle = runtime.handle();
+
+        let mut addrs = Vec::with_capacity(LISTENERS);
+        for listener_index in 0..LISTENERS {
+            let (addr_tx, addr_rx) = std::sync::mpsc::channel::<SocketAddr>();
+            drop(handle.spawn(async move {
+                let socket = TcpSocket::new_v4().expect("listener socket");
+                socket
+                    .set_reactor_affinity(Some(listener_index))
+                    .expect("listener affinity");
+                socket
+                    .bind("127.0.0.1:0".parse().expect("loopback addr"))
+                    .expect("bind listener");
+                let listener = socket.listen(128).expect("listen");
+                addr_tx
+                    .send(listener.local_addr().expect("listener addr"))
+                    .expect("publish listener addr");
+                let handle = Runtime::current_handle().expect("runtime handle in task");
+                for _ in 0..CONNECTIONS_PER_LISTENER {
+                    let (stream, _) = listener.accept().await.expect("accept");
+                    drop(handle.spawn(echo_server(stream)));
+                }
+            }));
+            addrs.push(
+                addr_rx
+                    .recv_timeout(Duration::from_secs(30))
+                    .expect("listener address"),
+            );
+        }
+
+        let clients = runtime.block_on(async {
+            let mut clients = Vec::with_capacity(LISTENERS * CONNECTIONS_PER_LISTENER);
+            for (listener_index, addr) in addrs.iter().enumerate() {
+                for _ in 0..CONNECTIONS_PER_LISTENER {
+                    let socket = TcpSocket::new_v4().expect("client socket");
+                    socket
+                        .set_reactor_affinity(Some(listener_index))
+                        .expect("client affinity");
+                    socket.set_nodelay(true).expect("client nodelay");
+                    clients.push(socket.connect(*addr).await.expect("connect"));
+                }
+            }
+            clients
+        });
+
+        Self { runtime, clients }
+    }
+
+    fn run_iteration(&mut self) {
+        let handle = self.runtime.handle();
+        let joins: Vec<_> = std::mem::take(&mut self.clients)
+            .into_iter()
+            .map(|client| handle.spawn(echo_client(client)))
+            .collect();
+        self.clients = self.runtime.block_on(async move {
+            let mut clients = Vec::with_capacity(joins.len());
+            for join in joins {
+                clients.push(join.await);
+            }
+            clients
+        });
+    }
+}
+
+async fn echo_server(mut stream: TcpStream) {
+    let mut buf = [0u8; MESSAGE.len()];
+    while stream.read_exact(&mut buf).await.is_ok() {
+        if stream.write_all(&buf).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn echo_client(mut stream: TcpStream) -> TcpStream {
+    let mut buf = [0u8; MESSAGE.len()];
+    for _ in 0..ROUNDS {
+        stream.write_all(&MESSAGE).await.expect("client write");
+        stream.read_exact(&mut buf).await.expect("client read");
+    }
+    stream
+}
+
+fn bench_multi_reactor_echo(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multi_reactor_echo");
+    group.throughput(Throughput::Elements((LISTENERS * CONNECTIONS_PER_LISTENER * ROUNDS) as u64));
+    for reactors in [1usize, 2, 4] {
+        let mut scenario = EchoScenario::new(reactors);
+        group.bench_with_input(BenchmarkId::from_parameter(reactors), &reactors, |b, _| {
+            b.iter(|| scenario.run_iteration());
+        });
+        for metrics in scenario.runtime.reactor_metrics() {
+            println!(
+                "reactors={reactors} reactor={} polls={} dispatched={} spurious={} wakeups={}",
+                metrics.reactor,
+                metrics.polls,
+                metrics.events_dispatched,
+                metrics.spurious_polls,
+                metrics.wakeups,
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_multi_reactor_echo);
+criterion_main!(benches);
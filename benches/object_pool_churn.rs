@@ -0,0 +1,90 @@
+//! Task-record churn with and without the per-worker object pool.
+//!
+//! Each iteration spawns and retires a batch of task records through the
+//! `TaskTable`, the same path the scheduler drives for short-lived tasks. A
+//! counting global allocator reports allocations per retired task alongside
+//! the criterion timings so the pool's allocation-rate reduction is visible
+//! directly, not only as a latency delta.
+
+use asupersync::runtime::TaskTable;
+use asupersync::types::{Budget, RegionId, TaskId, Time};
+use asupersync::util::ArenaIndex;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+// SAFETY: delegates every call to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: caller upholds `GlobalAlloc::alloc`'s contract.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: caller upholds `GlobalAlloc::dealloc`'s contract.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const BATCH: usize = 256;
+
+fn churn(table: &mut TaskTable, owner: RegionId, batch: usize) {
+    let mut live = Vec::with_capacity(batch);
+    for _ in 0..batch {
+        let idx = table.insert_pooled_task_with(|idx, record| {
+            record.id = TaskId::from_arena(idx);
+            record.owner = owner;
+            record.created_at = Time::from_nanos(1);
+            record.polls_remaining = Budget::INFINITE.poll_quota;
+        });
+        live.push(TaskId::from_arena(idx));
+    }
+    for task_id in live {
+        table.remove_and_recycle_task(black_box(task_id));
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn allocations_per_task(pool_limit: usize) -> f64 {
+    let mut table = TaskTable::with_capacity_and_pool_limit(BATCH, pool_limit);
+    let owner = RegionId::from_arena(ArenaIndex::new(1, 0));
+    churn(&mut table, owner, BATCH);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let rounds = 64;
+    for _ in 0..rounds {
+        churn(&mut table, owner, BATCH);
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    (after - before) as f64 / (rounds * BATCH) as f64
+}
+
+fn bench_task_record_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("task_record_churn");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    for (pool_limit, label) in [(0usize, "unpooled"), (BATCH, "pooled")] {
+        eprintln!(
+            "task_record_churn/{label}: {:.2} allocations per task",
+            allocations_per_task(pool_limit)
+        );
+        group.bench_with_input(BenchmarkId::new(label, BATCH), &pool_limit, |b, &limit| {
+            let mut table = TaskTable::with_capacity_and_pool_limit(BATCH, limit);
+            let owner = RegionId::from_arena(ArenaIndex::new(1, 0));
+            b.iter(|| churn(&mut table, owner, BATCH));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_task_record_churn);
+criterion_main!(benches);
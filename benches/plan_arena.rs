@@ -0,0 +1,114 @@
+//! Plan DAG construction with and without a reusable `PlanArena`.
+//!
+//! Each iteration builds and validates one request-sized plan: a fan-out of
+//! `race(primary, timeout(replica))` branches under a `join`, the shape a
+//! combinator-heavy service builds per request. A counting global allocator
+//! reports allocations per plan alongside the criterion timings.
+
+use asupersync::plan::{PlanArena, PlanDag, PlanId};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+// SAFETY: delegates every call to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: caller upholds `GlobalAlloc::alloc`'s contract.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: caller upholds `GlobalAlloc::dealloc`'s contract.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const TIMEOUT: Duration = Duration::from_millis(25);
+
+fn build_dag(fan_out: usize) -> PlanDag {
+    let mut dag = PlanDag::new();
+    let branches: Vec<PlanId> = (0..fan_out)
+        .map(|_| {
+            let primary = dag.leaf("primary");
+            let replica = dag.leaf("replica");
+            let bounded = dag.timeout(replica, TIMEOUT);
+            dag.race(vec![primary, bounded])
+        })
+        .collect();
+    let root = dag.join(branches);
+    dag.set_root(root);
+    dag.validate().expect("valid plan");
+    dag
+}
+
+/// `branches` is scratch kept across plans, like the arena itself.
+fn build_in_arena(arena: &mut PlanArena, branches: &mut Vec<PlanId>, fan_out: usize) -> usize {
+    let mut plan = arena.begin();
+    branches.clear();
+    for _ in 0..fan_out {
+        let primary = plan.leaf("primary");
+        let replica = plan.leaf("replica");
+        let bounded = plan.timeout(replica, TIMEOUT);
+        branches.push(plan.race([primary, bounded]));
+    }
+    let root = plan.join(branches.iter().copied());
+    plan.set_root(root);
+    plan.validate().expect("valid plan");
+    plan.node_count()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn allocations_per_plan(fan_out: usize, arena: Option<(&mut PlanArena, &mut Vec<PlanId>)>) -> f64 {
+    let rounds = 256;
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    match arena {
+        Some((arena, branches)) => {
+            for _ in 0..rounds {
+                black_box(build_in_arena(arena, branches, fan_out));
+            }
+        }
+        None => {
+            for _ in 0..rounds {
+                black_box(build_dag(fan_out));
+            }
+        }
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    (after - before) as f64 / f64::from(rounds)
+}
+
+fn bench_plan_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("plan_construction");
+
+    for fan_out in [2usize, 8, 32] {
+        let mut arena = PlanArena::new();
+        let mut branches = Vec::new();
+        build_in_arena(&mut arena, &mut branches, fan_out);
+        eprintln!(
+            "plan_construction/{fan_out}: {:.2} allocations per plan boxed, {:.2} in arena",
+            allocations_per_plan(fan_out, None),
+            allocations_per_plan(fan_out, Some((&mut arena, &mut branches))),
+        );
+        group.bench_with_input(BenchmarkId::new("boxed", fan_out), &fan_out, |b, &n| {
+            b.iter(|| black_box(build_dag(n)));
+        });
+        group.bench_with_input(BenchmarkId::new("arena", fan_out), &fan_out, |b, &n| {
+            b.iter(|| black_box(build_in_arena(&mut arena, &mut branches, n)));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_plan_construction);
+criterion_main!(benches);
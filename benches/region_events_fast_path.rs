@@ -0,0 +1,53 @@
+//! Cost of a region's lifecycle (create, cancel, drain, close) with and
+//! without region-event subscribers.
+//!
+//! With no subscriber every lifecycle hook stops at one relaxed atomic load,
+//! so the `no_subscriber` case is the regression guard: it should match a
+//! runtime without the event stream to within noise. `filtered_out`
+//! measures a subscriber whose filter rejects every event, and `subscribed`
+//! the full publish path.
+
+use asupersync::observability::{RegionEventFilter, RegionEventKind};
+use asupersync::runtime::RuntimeState;
+use asupersync::types::{Budget, CancelReason};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn bench_lifecycle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("region_events_lifecycle");
+    let filters = [
+        ("no_subscriber", None),
+        (
+            "filtered_out",
+            Some(RegionEventFilter::all().with_label_prefix("nothing.")),
+        ),
+        (
+            "subscribed",
+            Some(RegionEventFilter::all().with_kinds(RegionEventKind::ALL)),
+        ),
+    ];
+
+    for (label, filter) in filters {
+        group.bench_function(BenchmarkId::new("create_cancel_close", label), |b| {
+            let mut state = RuntimeState::new();
+            let root = state.create_root_region(Budget::INFINITE);
+            let _stream = filter
+                .clone()
+                .map(|filter| state.subscribe_region_events(filter));
+            let reason = CancelReason::user("bench");
+            b.iter(|| {
+                let child = state
+                    .create_child_region(root, Budget::INFINITE)
+                    .expect("child region should create");
+                let (tasks, wakes) = state.cancel_request(child, &reason, None).into_parts();
+                wakes.suppress();
+                black_box(tasks);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_lifecycle);
+criterion_main!(benches);
@@ -27,8 +27,8 @@ use asupersync::runtime::scheduler::local_queue::Stealer;
 use asupersync::runtime::scheduler::stealing::steal_task;
 use asupersync::runtime::scheduler::three_lane::AdaptiveBatchSizingProfile;
 use asupersync::runtime::scheduler::{
-    GlobalQueue, IntrusiveRing, IntrusiveStack, LocalQueue, Parker, QUEUE_TAG_READY, Scheduler,
-    SchedulerPlacementMode, ThreeLaneScheduler,
+    GlobalQueue, IntrusiveRing, IntrusiveStack, LocalQueue, Parker, QUEUE_TAG_READY,
+    RegionFairQueue, Scheduler, SchedulerPlacementMode, ThreeLaneScheduler,
 };
 use asupersync::runtime::{BlockingPool, BlockingPoolOptions, Runtime, RuntimeBuilder};
 use asupersync::sync::ContendedMutex;
@@ -751,6 +751,79 @@ fn bench_priority_scheduler(c: &mut Criterion) {
     group.finish();
 }
 
+/// Region-fair vs task-fair ready dispatch.
+///
+/// `single_region` is the no-regression guard: with one region, region-fair
+/// dispatch must stay in line with the plain task-fair ready lane.
+fn bench_region_fairness(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scheduler/region_fairness");
+
+    for &count in &[100u64, 1000] {
+        group.throughput(Throughput::Elements(count));
+        group.bench_with_input(BenchmarkId::new("task_fair", count), &count, |b, &count| {
+            let task_ids = tasks(count as usize);
+            b.iter_batched(
+                || (Scheduler::new(), task_ids.clone()),
+                |(mut scheduler, tasks)| {
+                    for t in &tasks {
+                        scheduler.schedule(*t, 0);
+                    }
+                    let mut hint = 0u64;
+                    while scheduler.pop_ready_only_with_hint(hint).is_some() {
+                        hint = hint.wrapping_add(1);
+                    }
+                    black_box(scheduler.is_empty())
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(
+            BenchmarkId::new("single_region", count),
+            &count,
+            |b, &count| {
+                let task_ids = tasks(count as usize);
+                b.iter_batched(
+                    || (RegionFairQueue::new(), task_ids.clone()),
+                    |(mut queue, tasks)| {
+                        for t in &tasks {
+                            queue.push(region(), *t, 0);
+                        }
+                        let mut hint = 0u64;
+                        while queue.pop(hint).is_some() {
+                            hint = hint.wrapping_add(1);
+                        }
+                        black_box(queue.is_empty())
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sixteen_regions", count),
+            &count,
+            |b, &count| {
+                let task_ids = tasks(count as usize);
+                b.iter_batched(
+                    || (RegionFairQueue::new(), task_ids.clone()),
+                    |(mut queue, tasks)| {
+                        for (i, t) in tasks.iter().enumerate() {
+                            queue.push(RegionId::new_for_test((i % 16) as u32, 0), *t, 0);
+                        }
+                        let mut hint = 0u64;
+                        while queue.pop(hint).is_some() {
+                            hint = hint.wrapping_add(1);
+                        }
+                        black_box(queue.is_empty())
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
 fn bench_priority_observability(c: &mut Criterion) {
     let mut group = c.benchmark_group("scheduler/priority_observability");
 
@@ -2354,6 +2427,7 @@ criterion_group!(
     bench_local_queue_push_many,
     bench_global_queue,
     bench_priority_scheduler,
+    bench_region_fairness,
     bench_priority_observability,
     bench_lane_priority,
     bench_work_stealing,
@@ -21,6 +21,9 @@
 //! Deferred quota and cancel request-tail reports are ordered-join observations,
 //! not first-terminal timestamps. Runtime teardown rows measure destructor-driven
 //! destruction of polled pending futures, not structured region close or drain.
+//! The live-task-limit group guards the admission fast path: a runtime without
+//! `max_live_tasks` must match one whose runtime or root-region subtree limit
+//! is never reached.
 //!
 //! Deterministic inputs; throughput in spawned tasks per second.
 
@@ -579,6 +582,39 @@ fn bench_join_handle_completion(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_live_task_limit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("live_task_limit");
+    group.throughput(Throughput::Elements(SPAWNS_PER_ITER as u64));
+    group.sample_size(20);
+
+    for (label, limit, region_limit) in [
+        ("unset", None, None),
+        ("unreached", Some(usize::MAX), None),
+        ("region_unreached", None, Some(usize::MAX)),
+    ] {
+        let mut builder = RuntimeBuilder::new()
+            .worker_threads(4)
+            .spawn_admission(SpawnAdmissionMode::Direct);
+        if let Some(limit) = limit {
+            builder = builder.max_live_tasks(limit);
+        }
+        if let Some(limit) = region_limit {
+            builder = builder.root_region_limits(RegionLimits {
+                max_live_tasks: Some(limit),
+                ..RegionLimits::UNLIMITED
+            });
+        }
+        let runtime = builder.build().expect("build benchmark runtime");
+        let completion = Arc::new(CompletionLatch::new());
+        group.bench_function(BenchmarkId::new("single_producer_latched", label), |b| {
+            b.iter(|| spawn_burst_single(black_box(&runtime), &completion));
+        });
+        drop(runtime);
+    }
+
+    group.finish();
+}
+
 fn bench_adversarial_tails(c: &mut Criterion) {
     if adversarial_reports_enabled() {
         {
@@ -665,6 +701,7 @@ criterion_group!(
     benches,
     bench_spawn_throughput,
     bench_join_handle_completion,
+    bench_live_task_limit,
     bench_adversarial_tails
 );
 criterion_main!(benches);
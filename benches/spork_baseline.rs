@@ -19,7 +19,7 @@ use std::sync::{Arc, Mutex};
 use criterion::{Criterion, criterion_group, criterion_main};
 
 use asupersync::cx::{Cx, NameRegistry, Scope};
-use asupersync::gen_server::{CallError, GenServer, Reply, SystemMsg};
+use asupersync::gen_server::{CallError, CastAdmission, GenServer, MailboxSpec, Reply, SystemMsg};
 use asupersync::lab::{LabConfig, LabRuntime};
 use asupersync::supervision::{ChildSpec, RestartPolicy, SupervisorBuilder};
 use asupersync::types::policy::FailFast;
@@ -151,6 +151,45 @@ fn bench_genserver_call(c: &mut Criterion) {
         })
     });
 
+    // Throughput guard for flow-controlled mailboxes: the same 10 casts as
+    // `cast_fire_and_forget`, through a spec'd bounded mailbox and through
+    // the explicit unbounded opt-in.
+    for (name, spec) in [
+        ("cast_bounded_fail_fast", MailboxSpec::bounded(32, CastAdmission::FailFast)),
+        ("cast_unbounded_opt_in", MailboxSpec::unbounded()),
+    ] {
+        group.bench_function(name, |b: &mut criterion::Bencher| {
+            b.iter(|| {
+                let budget = Budget::new().with_poll_quota(100_000);
+                let mut runtime = LabRuntime::new(LabConfig::new(42));
+                let region = runtime.state.create_root_region(budget);
+                let cx = Cx::for_testing();
+                let scope = Scope::<FailFast>::new(region, budget);
+
+                let (handle, stored) = scope
+                    .spawn_gen_server_with_mailbox(
+                        &mut runtime.state,
+                        &cx,
+                        BenchCounter { count: 0 },
+                        spec,
+                    )
+                    .unwrap();
+                let server_task_id = handle.task_id();
+                runtime.state.store_spawned_task(server_task_id, stored);
+
+                let server_ref = handle.server_ref();
+                for i in 0..10 {
+                    let _ = server_ref.try_cast(BenchCast::Add(i));
+                }
+
+                runtime.scheduler.lock().schedule(server_task_id, 0);
+                runtime.run_until_idle();
+
+                std::hint::black_box(server_ref.mailbox_metrics())
+            })
+        });
+    }
+
     group.bench_function("spawn_server", |b: &mut criterion::Bencher| {
         b.iter(|| {
             let budget = Budget::new().with_poll_quota(100_000);
@@ -0,0 +1,94 @@
+//! TCP byte tap overhead benchmark.
+//!
+//! Drives a loopback echo connection through a fixed number of ping-pong
+//! rounds, once with no tap attached and once with a tap capturing both
+//! directions into a `RingSink`. The untapped case is the baseline the tap
+//! hooks must not regress.
+//!
+//! Gated behind `criterion-benches`; run with
+//! `cargo bench --bench tcp_tap_overhead --features criterion-benches`.
+
+#![allow(missing_docs)]
+
+use asupersync::io::{AsyncReadExt, AsyncWriteExt};
+use asupersync::net::{RingSink, TapBudget, TapConfig, TapDirection, TcpListener, TcpStream};
+use asupersync::runtime::builder::{Runtime, RuntimeBuilder};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::sync::Arc;
+
+const ROUNDS: usize = 64;
+const MESSAGE: [u8; 512] = [0x5a; 512];
+
+struct EchoScenario {
+    runtime: Runtime,
+    client: Option<TcpStream>,
+}
+
+impl EchoScenario {
+    fn new(tapped: bool) -> Self {
+        let runtime = RuntimeBuilder::new()
+            .worker_threads(2)
+            .build()
+            .expect("build benchmark runtime");
+        let handle = runtime.handle();
+        let client = runtime.block_on(async move {
+            let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind listener");
+            let addr = listener.local_addr().expect("listener addr");
+            drop(handle.spawn(async move {
+                let (stream, _) = listener.accept().await.expect("accept");
+                echo_server(stream).await;
+            }));
+            let mut client = TcpStream::connect(addr).await.expect("connect");
+            client.set_nodelay(true).expect("client nodelay");
+            if tapped {
+                let config =
+                    TapConfig::new(TapDirection::Both, usize::MAX, Arc::new(RingSink::new(256)))
+                        .with_budget(Arc::new(TapBudget::new(usize::MAX)));
+                client.tap(config).expect("attach tap");
+            }
+            client
+        });
+        Self {
+            runtime,
+            client: Some(client),
+        }
+    }
+
+    fn run_iteration(&mut self) {
+        let client = self.client.take().expect("client present");
+        self.client = Some(self.runtime.block_on(echo_client(client)));
+    }
+}
+
+async fn echo_server(mut stream: TcpStream) {
+    let mut buf = [0u8; MESSAGE.len()];
+    while stream.read_exact(&mut buf).await.is_ok() {
+        if stream.write_all(&buf).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn echo_client(mut stream: TcpStream) -> TcpStream {
+    let mut buf = [0u8; MESSAGE.len()];
+    for _ in 0..ROUNDS {
+        stream.write_all(&MESSAGE).await.expect("client write");
+        stream.read_exact(&mut buf).await.expect("client read");
+    }
+    stream
+}
+
+fn bench_tcp_tap_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tcp_tap_overhead");
+    group.throughput(Throughput::Bytes((ROUNDS * MESSAGE.len() * 2) as u64));
+    for (name, tapped) in [("untapped", false), ("tapped", true)] {
+        let mut scenario = EchoScenario::new(tapped);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &tapped, |b, _| {
+            b.iter(|| scenario.run_iteration());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tcp_tap_overhead);
+criterion_main!(benches);
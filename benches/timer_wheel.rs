@@ -9,6 +9,11 @@
 //! - Time advancement/tick (O(expired) expected)
 //! - Large-scale scenarios (10K timers)
 //! - Coalescing overhead
+//! - Capacity: 10M outstanding timers across mixed priority classes
+//!
+//! ## Driver benchmarks
+//! - Connection churn: per-timer `register`/`update`/`cancel` against
+//!   `TimerBatch` `register_many`/`reset_many`/`cancel_many`
 //!
 //! ## Comparison benchmarks
 //! - BTreeMap<u64, Vec<Waker>> — ordered map (O(log n) insert/remove)
@@ -81,6 +86,19 @@
 //! for connection timeout management where most timers are cancelled before
 //! expiry (typical server pattern).
 //!
+//! ## Capacity (10M outstanding timers)
+//!
+//! Run: `cargo bench --features "test-internals criterion-benches" --bench timer_wheel -- capacity/`
+//!
+//! Every outstanding timer costs at most `TIMER_ENTRY_BYTES` (56 bytes on
+//! 64-bit targets): a 32-byte wheel entry (deadline, waker, id, generation)
+//! plus a 24-byte liveness slot (generation, packed slot hint, class). At
+//! 10M timers that is 560 MB (~534 MiB) of entry storage. Bucket `Vec`
+//! growth can add up to one entry's worth of slack per bucket in the worst
+//! case; the benchmark prints the entry-storage figure alongside its timing.
+//! The `size_of` budget is enforced at compile time, so this table only
+//! moves when the entry layout does.
+//!
 //! History: an earlier table measured on the default `bench` profile recorded
 //! cancel at 2.67x and mixed as roughly competitive; wheel-side optimization
 //! work plus the LTO/codegen-units=1 release-perf profile moved every
@@ -96,7 +114,10 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::task::{Wake, Waker};
 use std::time::Duration;
 
-use asupersync::time::{CoalescingConfig, TimerWheel, TimerWheelConfig};
+use asupersync::time::{
+    CoalescingConfig, TIMER_ENTRY_BYTES, TimerClass, TimerDriverHandle, TimerWheel,
+    TimerWheelConfig, VirtualClock,
+};
 use asupersync::types::Time;
 
 // =============================================================================
@@ -1212,6 +1233,142 @@ fn bench_comparison_memory(c: &mut Criterion) {
     group.finish();
 }
 
+// =============================================================================
+// CONNECTION CHURN: SINGLE VS BATCHED DRIVER OPERATIONS
+// =============================================================================
+
+const CHURN_CONNECTIONS: u64 = 1_000;
+const CHURN_RESETS: u64 = 4;
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A virtual-clock driver with a standing population of long-lived timers,
+/// so per-connection cancels never empty the wheel.
+fn churn_driver() -> (Arc<VirtualClock>, TimerDriverHandle) {
+    let clock = Arc::new(VirtualClock::new());
+    let timer = TimerDriverHandle::with_virtual_clock(Arc::clone(&clock));
+    for _ in 0..1_000 {
+        let _ = timer.register(Time::from_secs(86_400), noop_waker());
+    }
+    (clock, timer)
+}
+
+/// Moves virtual time on a second so slots holding cancelled entries drain
+/// and the wheel does not grow across iterations.
+fn churn_tick(clock: &VirtualClock, timer: &TimerDriverHandle) {
+    clock.advance(1_000_000_000);
+    std::hint::black_box(timer.process_timers());
+}
+
+/// Per-connection timer lifecycle at high churn: idle, header-read, and
+/// keep-alive timers armed on accept, the idle timer pushed out on each of
+/// `CHURN_RESETS` requests, and everything cancelled on teardown.
+///
+/// Driver lock acquisitions per connection:
+/// - single: 3 `register` + 4 `update` + 3 `cancel` = 10
+/// - batched: 1 `register_many` + 4 `reset_many` + 1 `cancel_many` = 6
+///
+/// Each `update` cancels the idle timer and pushes a fresh entry into a wheel
+/// slot, leaving the dead one behind until its slot is drained (4 extra slot
+/// entries, and slot `Vec` growth, per connection). The batched resets stay
+/// within the idle timer's 256ms level-1 slot, so each rewrites the deadline
+/// in place and allocates nothing; the batch path's only allocation is the
+/// handle `Vec` returned by `register_many`.
+fn bench_connection_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("timer_driver/connection_churn");
+    group.throughput(Throughput::Elements(CHURN_CONNECTIONS));
+
+    group.bench_function("single", |b: &mut criterion::Bencher| {
+        let (clock, timer) = churn_driver();
+        b.iter(|| {
+            for _ in 0..CHURN_CONNECTIONS {
+                let now = timer.now();
+                let mut handles = [
+                    timer.register(now + IDLE_TIMEOUT, noop_waker()),
+                    timer.register(now + HEADER_TIMEOUT, noop_waker()),
+                    timer.register(now + KEEP_ALIVE_TIMEOUT, noop_waker()),
+                ];
+                for request in 1..=CHURN_RESETS {
+                    let deadline = now + IDLE_TIMEOUT + Duration::from_millis(request * 10);
+                    handles[0] = timer.update(&handles[0], deadline, noop_waker());
+                }
+                for handle in &handles {
+                    std::hint::black_box(timer.cancel(handle));
+                }
+            }
+            churn_tick(&clock, &timer);
+        });
+    });
+
+    group.bench_function("batched", |b: &mut criterion::Bencher| {
+        let (clock, timer) = churn_driver();
+        b.iter(|| {
+            for _ in 0..CHURN_CONNECTIONS {
+                let now = timer.now();
+                let batch = timer.batch();
+                let handles = batch.register_many([
+                    (now + IDLE_TIMEOUT, noop_waker()),
+                    (now + HEADER_TIMEOUT, noop_waker()),
+                    (now + KEEP_ALIVE_TIMEOUT, noop_waker()),
+                ]);
+                for request in 1..=CHURN_RESETS {
+                    let deadline = now + IDLE_TIMEOUT + Duration::from_millis(request * 10);
+                    std::hint::black_box(batch.reset_many(&handles[..1], deadline));
+                }
+                std::hint::black_box(batch.cancel_many(&handles));
+            }
+            churn_tick(&clock, &timer);
+        });
+    });
+
+    group.finish();
+}
+
+// =============================================================================
+// CAPACITY: 10M OUTSTANDING TIMERS
+// =============================================================================
+
+const CAPACITY_TIMERS: u64 = 10_000_000;
+
+fn bench_capacity_10m(c: &mut Criterion) {
+    let mut group = c.benchmark_group("capacity");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(60));
+    group.throughput(Throughput::Elements(CAPACITY_TIMERS));
+
+    let entry_bytes = TIMER_ENTRY_BYTES as u64 * CAPACITY_TIMERS;
+    eprintln!(
+        "capacity: {CAPACITY_TIMERS} timers x {TIMER_ENTRY_BYTES} B = {entry_bytes} B ({} MiB)",
+        entry_bytes >> 20
+    );
+
+    group.bench_function("register_10m_mixed_classes", |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                let mut wheel = TimerWheel::new();
+                let start = std::time::Instant::now();
+                for i in 0..CAPACITY_TIMERS {
+                    // Spread deadlines over an hour so every wheel level holds timers.
+                    let deadline = Time::from_millis(i % 3_600_000 + 1);
+                    let class = TimerClass::ALL[(i % 4) as usize];
+                    wheel.register_with_class(deadline, noop_waker(), class);
+                }
+                total += start.elapsed();
+
+                let metrics = wheel.metrics();
+                assert_eq!(metrics.outstanding as u64, CAPACITY_TIMERS);
+                assert_eq!(metrics.class(TimerClass::Critical) as u64, CAPACITY_TIMERS / 4);
+                std::hint::black_box(wheel);
+            }
+            total
+        });
+    });
+
+    group.finish();
+}
+
 // =============================================================================
 // MAIN
 // =============================================================================
@@ -1225,6 +1382,8 @@ criterion_group!(
     bench_coalescing,
     bench_overflow,
     bench_config,
+    bench_connection_churn,
+    bench_capacity_10m,
 );
 
 criterion_group!(
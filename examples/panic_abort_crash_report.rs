@@ -0,0 +1,27 @@
+//! Crash report on `panic = "abort"`.
+//!
+//! Run with `CARGO_PROFILE_DEV_PANIC=abort cargo run --example
+//! panic_abort_crash_report -- /tmp/crash.txt`: the spawned task panics, the
+//! process aborts, and the report is left at the given path. In an unwinding
+//! build the panic is contained and no report is written.
+
+use asupersync::runtime::{CrashReportConfig, JoinHandle, PanicStrategy, RuntimeBuilder};
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "asupersync-crash-report.txt".to_string());
+    let runtime = RuntimeBuilder::current_thread()
+        .crash_report(CrashReportConfig::new(&path).with_max_events(16))
+        .build()
+        .expect("runtime");
+    println!("panic strategy: {}", PanicStrategy::current().as_str());
+
+    let handle: JoinHandle<()> = runtime.handle().spawn(async {
+        panic!("crash report example task panicked");
+    });
+    let joined = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        runtime.block_on(handle);
+    }));
+    println!("task panic contained: {}", joined.is_err());
+}
@@ -9,6 +9,9 @@
 //! frankenlab run examples/scenarios/01_race_condition.yaml
 //! frankenlab explore examples/scenarios/01_race_condition.yaml --seeds 1000
 //! frankenlab replay examples/scenarios/01_race_condition.yaml
+//! frankenlab watch examples/scenarios/01_race_condition.yaml
+//! frankenlab trace train-dict soak.trace --output soak.dict
+//! frankenlab analyze run-trace.json --critical-path --flamegraph
 //! ```
 
 use asupersync::config::EncodingConfig;
@@ -16,11 +19,13 @@ use asupersync::lab::ldfi::HittingSetBudget;
 use asupersync::lab::ldfi_trace::{
     LdfiReport, TraceLineageConfig, blind_chaos_single_fault_count, ldfi_report, support_graph_for,
 };
-use asupersync::lab::scenario::Scenario;
+use asupersync::lab::scenario::{IncludeRef, Scenario};
 use asupersync::lab::scenario_runner::{
     ScenarioExplorationResult, ScenarioRunResult, ScenarioRunner, ScenarioRunnerError,
 };
 use asupersync::runtime::RuntimeBuilder;
+use asupersync::trace::analysis::{critical_path, folded_stacks};
+use asupersync::trace::framed::{DEFAULT_DICTIONARY_SIZE, TraceDictionary};
 use asupersync::trace::minimizer::LogicalMinimizerClock;
 use asupersync::trace::raptorq_journal_writer::{
     DurableJournalError, DurableTraceJournal, DurableTraceJournalConfig,
@@ -30,7 +35,7 @@ use asupersync::trace::{
     IncidentReplayMinimizationVerdict, IncidentReplayOracle, IncidentReplayPackage,
     IncidentReplaySourceRole, ScenarioElement, TraceMinimizer, minimize_incident_replay_package,
 };
-use asupersync::trace::{TraceData, TraceEvent, TraceEventKind};
+use asupersync::trace::{TraceData, TraceEvent, TraceEventKind, TraceReader};
 use clap::{ArgAction, Args, Parser, Subcommand};
 use serde::Serialize;
 use std::cell::{Cell, RefCell};
@@ -39,6 +44,8 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+mod watch;
+
 #[derive(Parser, Debug)]
 #[command(
     name = "frankenlab",
@@ -65,6 +72,9 @@ enum Command {
     /// Validate a scenario YAML file without executing it
     Validate(ValidateArgs),
 
+    /// Re-validate and re-run a scenario whenever it or its includes change
+    Watch(WatchArgs),
+
     /// Replay a scenario twice and verify determinism
     Replay(ReplayArgs),
 
@@ -82,6 +92,12 @@ enum Command {
 
     /// Run lineage-driven fault injection over a recorded trace
     Ldfi(LdfiArgs),
+
+    /// Binary trace file utilities
+    Trace(TraceArgs),
+
+    /// Analyze a recorded trace: critical path and flamegraph stacks
+    Analyze(AnalyzeArgs),
 }
 
 #[derive(Args, Debug)]
@@ -100,6 +116,24 @@ struct ValidateArgs {
     scenario: PathBuf,
 }
 
+#[derive(Args, Debug)]
+struct WatchArgs {
+    /// Path to the scenario YAML file
+    scenario: PathBuf,
+
+    /// Override the seed from the scenario file for every re-run
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// How often to poll the watched files, in milliseconds
+    #[arg(long, default_value_t = 100)]
+    poll_ms: u64,
+
+    /// Quiet period after the last change before re-running, in milliseconds
+    #[arg(long, default_value_t = 250)]
+    debounce_ms: u64,
+}
+
 #[derive(Args, Debug)]
 struct ReplayArgs {
     /// Path to the scenario YAML file
@@ -198,6 +232,52 @@ struct LdfiArgs {
     max_hypotheses: usize,
 }
 
+#[derive(Args, Debug)]
+struct AnalyzeArgs {
+    /// Path to the recorded trace: a JSON array of `TraceEvent`s, or one event
+    /// per line (a truncated last line is tolerated)
+    trace: PathBuf,
+
+    /// Report the critical path through task dependencies (the default)
+    #[arg(long, action = ArgAction::SetTrue)]
+    critical_path: bool,
+
+    /// Emit folded stacks for `inferno-flamegraph` / `flamegraph.pl`
+    #[arg(long, action = ArgAction::SetTrue)]
+    flamegraph: bool,
+}
+
+#[derive(Args, Debug)]
+struct TraceArgs {
+    #[command(subcommand)]
+    command: TraceCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum TraceCommand {
+    /// Train a zstd dictionary for small-event traces from sample trace files
+    TrainDict(TrainDictArgs),
+}
+
+#[derive(Args, Debug)]
+struct TrainDictArgs {
+    /// Binary trace files to sample events from
+    #[arg(required = true)]
+    traces: Vec<PathBuf>,
+
+    /// Where to write the trained dictionary
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// Maximum dictionary size in bytes
+    #[arg(long, default_value_t = DEFAULT_DICTIONARY_SIZE)]
+    max_size: usize,
+
+    /// Maximum number of events to sample across all traces; 0 means unlimited
+    #[arg(long, default_value_t = 100_000)]
+    max_samples: usize,
+}
+
 // ---------------------------------------------------------------------------
 // Scenario loading
 // ---------------------------------------------------------------------------
@@ -212,9 +292,144 @@ fn parse_scenario(path: &Path, yaml: &str) -> Result<asupersync::lab::scenario::
 }
 
 fn load_scenario(path: &Path) -> Result<asupersync::lab::scenario::Scenario, String> {
-    let yaml =
-        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
-    parse_scenario(path, &yaml)
+    load_scenario_with_sources(path).map(|loaded| loaded.scenario)
+}
+
+/// A scenario after `include:` expansion, with every file that contributed
+/// to it (the root file first, then includes in resolution order).
+#[derive(Debug, Clone)]
+struct LoadedScenario {
+    scenario: Scenario,
+    sources: Vec<PathBuf>,
+}
+
+fn load_scenario_with_sources(path: &Path) -> Result<LoadedScenario, String> {
+    let mut chain = Vec::new();
+    let mut sources = Vec::new();
+    let value = expand_includes(path, &mut chain, &mut sources)?;
+    let scenario = serde_yaml::from_value(value).map_err(|e| {
+        format!(
+            "Failed to parse {}: {e}. Hint: check indentation and field names",
+            path.display()
+        )
+    })?;
+    Ok(LoadedScenario { scenario, sources })
+}
+
+fn include_chain(chain: &[PathBuf], last: Option<&Path>) -> String {
+    chain
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(last)
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn included_from(chain: &[PathBuf]) -> String {
+    if chain.is_empty() {
+        String::new()
+    } else {
+        format!(" (include chain: {})", include_chain(chain, None))
+    }
+}
+
+/// Reads `path` and merges its includes underneath it, depth-first.
+///
+/// `chain` is the stack of files currently being expanded and is what makes
+/// a cycle detectable (and reportable); a file reached twice along different
+/// branches is fine. Merge rules are documented on [`IncludeRef`].
+///
+/// [`IncludeRef`]: asupersync::lab::scenario::IncludeRef
+fn expand_includes(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+    sources: &mut Vec<PathBuf>,
+) -> Result<serde_yaml::Value, String> {
+    // Any error aborts the whole load, so `chain` is only unwound on success.
+    let context = included_from(chain);
+    let canonical = fs::canonicalize(path)
+        .map_err(|e| format!("Failed to read {}: {e}{context}", path.display()))?;
+    if let Some(start) = chain.iter().position(|seen| *seen == canonical) {
+        return Err(format!(
+            "Include cycle detected: {}",
+            include_chain(&chain[start..], Some(canonical.as_path()))
+        ));
+    }
+
+    let yaml = fs::read_to_string(&canonical)
+        .map_err(|e| format!("Failed to read {}: {e}{context}", path.display()))?;
+    let parse_error = |e: serde_yaml::Error| {
+        format!(
+            "Failed to parse {}: {e}{context}. Hint: check indentation and field names",
+            path.display()
+        )
+    };
+    let mut document =
+        match serde_yaml::from_str::<serde_yaml::Value>(&yaml).map_err(parse_error)? {
+            serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+            serde_yaml::Value::Mapping(mapping) => mapping,
+            _ => {
+                return Err(format!(
+                    "Failed to parse {}: a scenario document must be a YAML mapping{context}",
+                    path.display()
+                ));
+            }
+        };
+    let include_value = document.remove("include");
+    let includes: Vec<IncludeRef> = match &include_value {
+        Some(value) => serde_yaml::from_value(value.clone()).map_err(parse_error)?,
+        None => Vec::new(),
+    };
+    if !sources.contains(&canonical) {
+        sources.push(canonical.clone());
+    }
+
+    let base_dir = canonical
+        .parent()
+        .map_or_else(PathBuf::new, Path::to_path_buf);
+    chain.push(canonical);
+    let mut merged = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    for (index, include) in includes.iter().enumerate() {
+        if let Some(reason) = include.path_error() {
+            return Err(format!(
+                "{}: include[{index}].path: {reason} (include chain: {})",
+                path.display(),
+                include_chain(chain, None)
+            ));
+        }
+        let fragment = expand_includes(&base_dir.join(&include.path), chain, sources)?;
+        merge_yaml(&mut merged, fragment);
+    }
+    chain.pop();
+
+    merge_yaml(&mut merged, serde_yaml::Value::Mapping(document));
+    // The root keeps its own include list so `validate` still sees it.
+    if chain.is_empty()
+        && let Some(value) = include_value
+        && let serde_yaml::Value::Mapping(mapping) = &mut merged
+    {
+        mapping.insert("include".into(), value);
+    }
+    Ok(merged)
+}
+
+/// Overlays `overlay` onto `base`: mappings merge key by key (recursively),
+/// every other value is replaced.
+fn merge_yaml(base: &mut serde_yaml::Value, overlay: serde_yaml::Value) {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(base), serde_yaml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_yaml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 fn runner_error_message(err: ScenarioRunnerError) -> String {
@@ -1258,6 +1473,126 @@ fn cmd_ldfi(args: &LdfiArgs, json: bool) -> Result<(), String> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Trace utilities
+// ---------------------------------------------------------------------------
+
+fn cmd_trace(args: TraceArgs, json: bool) -> Result<(), String> {
+    match args.command {
+        TraceCommand::TrainDict(args) => cmd_trace_train_dict(&args, json),
+    }
+}
+
+/// Samples events from the given traces, trains a zstd dictionary on them and
+/// writes it to `--output`. Traces compressed with the dictionary record its
+/// id and checksum, so readers must be handed the same file.
+fn cmd_trace_train_dict(args: &TrainDictArgs, json: bool) -> Result<(), String> {
+    let limit = if args.max_samples == 0 {
+        usize::MAX
+    } else {
+        args.max_samples
+    };
+    let mut samples = Vec::new();
+    for path in &args.traces {
+        if samples.len() >= limit {
+            break;
+        }
+        let reader =
+            TraceReader::open(path).map_err(|e| format!("open trace {}: {e}", path.display()))?;
+        for event in reader.events().take(limit - samples.len()) {
+            samples.push(event.map_err(|e| format!("read trace {}: {e}", path.display()))?);
+        }
+    }
+    if samples.is_empty() {
+        return Err("no events to train on".to_string());
+    }
+
+    let dictionary = TraceDictionary::train(&samples, args.max_size)
+        .map_err(|e| format!("train dictionary: {e}"))?;
+    fs::write(&args.output, dictionary.as_bytes())
+        .map_err(|e| format!("write dictionary to {}: {e}", args.output.display()))?;
+
+    if json {
+        let report = serde_json::json!({
+            "output": args.output.display().to_string(),
+            "samples": samples.len(),
+            "dictionary_id": dictionary.id(),
+            "checksum": format!("{:#010x}", dictionary.checksum()),
+            "bytes": dictionary.as_bytes().len(),
+        });
+        println!("{}", pretty_json_or(&report, ""));
+    } else {
+        println!(
+            "Trained dictionary {} (checksum {:#010x}, {} bytes) from {} event(s); wrote {}",
+            dictionary.id(),
+            dictionary.checksum(),
+            dictionary.as_bytes().len(),
+            samples.len(),
+            args.output.display()
+        );
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Analyze
+// ---------------------------------------------------------------------------
+
+/// Reads a JSON trace for `analyze`: either a `Vec<TraceEvent>` array or one
+/// event per line. Line-oriented traces are read tolerantly: a damaged record
+/// ends the read, the events before it are kept, and the parse error is
+/// returned alongside them.
+fn read_trace_events(path: &Path) -> Result<(Vec<TraceEvent>, Option<String>), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("read trace {}: {e}", path.display()))?;
+    if content.trim_start().starts_with('[') {
+        let trace = serde_json::from_str(&content)
+            .map_err(|e| format!("parse trace {}: {e}", path.display()))?;
+        return Ok((trace, None));
+    }
+    let mut trace = Vec::new();
+    for event in serde_json::Deserializer::from_str(&content).into_iter::<TraceEvent>() {
+        match event {
+            Ok(event) => trace.push(event),
+            Err(e) => return Ok((trace, Some(e.to_string()))),
+        }
+    }
+    Ok((trace, None))
+}
+
+fn cmd_analyze(args: &AnalyzeArgs, json: bool) -> Result<(), String> {
+    let (trace, damage) = read_trace_events(&args.trace)?;
+    if let Some(error) = &damage {
+        eprintln!(
+            "Warning: {} is damaged ({error}); analyzing the {} event(s) before it",
+            args.trace.display(),
+            trace.len()
+        );
+    }
+
+    let report = (args.critical_path || !args.flamegraph).then(|| critical_path(&trace));
+    let folded = args.flamegraph.then(|| folded_stacks(&trace));
+
+    if json {
+        let output = serde_json::json!({
+            "trace": args.trace.display().to_string(),
+            "events": trace.len(),
+            "damaged": damage.is_some(),
+            "critical_path": report,
+            "folded_stacks": folded,
+        });
+        println!("{}", pretty_json_or(&output, ""));
+    } else {
+        if let Some(report) = &report {
+            print!("{report}");
+        }
+        if let Some(folded) = &folded {
+            print!("{folded}");
+        }
+    }
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Main
 // ---------------------------------------------------------------------------
@@ -1268,12 +1603,15 @@ fn main() -> ExitCode {
     let result = match cli.command {
         Command::Run(args) => cmd_run(args, cli.json),
         Command::Validate(args) => cmd_validate(args, cli.json),
+        Command::Watch(args) => watch::cmd_watch(args, cli.json),
         Command::Replay(args) => cmd_replay(args, cli.json),
         Command::Explore(args) => cmd_explore(args, cli.json),
         Command::Minimize(args) => cmd_minimize(args, cli.json),
         Command::Demo(args) => cmd_demo(args, cli.json),
         Command::TraceRecover(args) => cmd_trace_recover(args, cli.json),
         Command::Ldfi(args) => cmd_ldfi(&args, cli.json),
+        Command::Trace(args) => cmd_trace(args, cli.json),
+        Command::Analyze(args) => cmd_analyze(&args, cli.json),
     };
 
     match result {
@@ -1297,6 +1635,102 @@ mod tests {
     };
     use std::collections::BTreeMap;
 
+    /// A fresh, per-process scratch directory under the system temp dir.
+    pub fn scratch_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("frankenlab_{tag}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create scratch dir");
+        dir
+    }
+
+    fn write_file(path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create parent dir");
+        }
+        fs::write(path, contents).expect("write scenario file");
+    }
+
+    #[test]
+    fn include_expansion_merges_fragments_under_the_including_file() {
+        let dir = scratch_dir("include_merge");
+        write_file(
+            &dir.join("shared/base.yaml"),
+            "include:\n  - path: lab.yaml\ndescription: from base\noracles: [quiescence]\n",
+        );
+        write_file(
+            &dir.join("shared/lab.yaml"),
+            "lab:\n  seed: 5\n  worker_count: 3\n  max_steps: 500\n",
+        );
+        write_file(
+            &dir.join("scenario.yaml"),
+            "id: include-merge\ninclude:\n  - path: shared/base.yaml\nlab:\n  seed: 11\n",
+        );
+
+        let loaded = load_scenario_with_sources(&dir.join("scenario.yaml")).expect("loads");
+        let scenario = &loaded.scenario;
+        assert_eq!(scenario.id, "include-merge");
+        assert_eq!(scenario.description, "from base");
+        assert_eq!(scenario.oracles, ["quiescence"]);
+        // Nested mapping merge: the root overrides one key, keeps the rest.
+        assert_eq!(scenario.lab.seed, 11);
+        assert_eq!(scenario.lab.worker_count, 3);
+        assert_eq!(scenario.lab.max_steps, Some(500));
+        assert_eq!(scenario.include.len(), 1);
+        assert!(scenario.validate().is_empty());
+
+        let names: Vec<_> = loaded
+            .sources
+            .iter()
+            .map(|path| path.file_name().expect("file name").to_owned())
+            .collect();
+        assert_eq!(names, ["scenario.yaml", "base.yaml", "lab.yaml"]);
+    }
+
+    #[test]
+    fn include_cycles_and_unsafe_paths_report_the_chain() {
+        let dir = scratch_dir("include_cycle");
+        write_file(&dir.join("a.yaml"), "id: a\ninclude:\n  - path: b.yaml\n");
+        write_file(&dir.join("b.yaml"), "include:\n  - path: c.yaml\n");
+        write_file(&dir.join("c.yaml"), "include:\n  - path: b.yaml\n");
+
+        let err = load_scenario(&dir.join("a.yaml")).expect_err("cycle must be rejected");
+        assert!(err.starts_with("Include cycle detected: "), "{err}");
+        let chain: Vec<_> = err
+            .trim_start_matches("Include cycle detected: ")
+            .split(" -> ")
+            .map(|path| Path::new(path).file_name().expect("file name").to_owned())
+            .collect();
+        assert_eq!(chain, ["b.yaml", "c.yaml", "b.yaml"]);
+
+        // The same fragment reached along two branches is not a cycle.
+        write_file(&dir.join("shared.yaml"), "description: shared\n");
+        write_file(&dir.join("left.yaml"), "include:\n  - path: shared.yaml\n");
+        write_file(
+            &dir.join("diamond.yaml"),
+            "id: diamond\ninclude:\n  - path: left.yaml\n  - path: shared.yaml\n",
+        );
+        let loaded = load_scenario_with_sources(&dir.join("diamond.yaml")).expect("diamond loads");
+        assert_eq!(loaded.sources.len(), 3);
+
+        write_file(
+            &dir.join("escape.yaml"),
+            "id: escape\ninclude:\n  - path: left.yaml\n",
+        );
+        write_file(
+            &dir.join("left.yaml"),
+            "include:\n  - path: ../secrets.yaml\n",
+        );
+        let err = load_scenario(&dir.join("escape.yaml")).expect_err("traversal rejected");
+        assert!(err.contains("include[0].path"), "{err}");
+        assert!(err.contains("path traversal"), "{err}");
+        assert!(err.contains("escape.yaml -> "), "{err}");
+
+        write_file(&dir.join("left.yaml"), "include:\n  - path: missing.yaml\n");
+        let err = load_scenario(&dir.join("escape.yaml")).expect_err("missing include");
+        assert!(err.contains("missing.yaml"), "{err}");
+        assert!(err.contains("include chain: "), "{err}");
+    }
+
     #[test]
     fn ldfi_cli_core_finds_single_shared_fault() {
         use asupersync::remote::NodeId;
@@ -1768,4 +2202,87 @@ mod tests {
             MinimizeInput::ScenarioYaml(_) => panic!("expected incident replay package input"),
         }
     }
+
+    #[test]
+    fn analyze_reads_damaged_line_traces_up_to_the_damage() {
+        use asupersync::types::{RegionId, TaskId, Time};
+
+        let (task, region) = (TaskId::new_for_test(1, 0), RegionId::new_for_test(0, 0));
+        let events = [
+            TraceEvent::spawn(0, Time::ZERO, task, region),
+            TraceEvent::poll(1, Time::ZERO, task, region),
+            TraceEvent::complete(2, Time::from_millis(5), task, region),
+        ];
+        let mut lines: Vec<String> = events
+            .iter()
+            .map(|event| serde_json::to_string(event).expect("serialize"))
+            .collect();
+        let last = lines.pop().expect("last line");
+        lines.push(last[..last.len() / 2].to_string());
+
+        let dir = scratch_dir("analyze");
+        let path = dir.join("run.ndjson");
+        write_file(&path, &lines.join("\n"));
+        let (trace, damage) = read_trace_events(&path).expect("tolerant read");
+        assert_eq!(trace, events[..2]);
+        assert!(damage.is_some());
+
+        // The cut-off completion leaves the task's span open and truncated.
+        let report = critical_path(&trace);
+        assert_eq!(report.truncated_spans, 1);
+
+        let array = dir.join("run.json");
+        write_file(&array, &serde_json::to_string(&events).expect("serialize"));
+        let (trace, damage) = read_trace_events(&array).expect("array read");
+        assert_eq!(trace, events);
+        assert!(damage.is_none());
+        assert_eq!(folded_stacks(&trace), "R0;T1 5000000\n");
+
+        let args = AnalyzeArgs {
+            trace: array,
+            critical_path: true,
+            flamegraph: true,
+        };
+        cmd_analyze(&args, true).expect("analyze");
+    }
+
+    #[test]
+    fn trace_train_dict_writes_a_loadable_dictionary() {
+        use asupersync::trace::replay::{CompactTaskId, ReplayEvent};
+
+        let dir = scratch_dir("train_dict");
+        let trace = dir.join("soak.trace");
+        let events: Vec<ReplayEvent> = (0..2_000_u64)
+            .map(|i| match i % 3 {
+                0 => ReplayEvent::TaskScheduled {
+                    task: CompactTaskId(((i % 24) << 32) | 1),
+                    at_tick: i,
+                },
+                1 => ReplayEvent::TimerCreated {
+                    timer_id: i,
+                    deadline_nanos: i * 1_000_000,
+                },
+                _ => ReplayEvent::TimeAdvanced {
+                    from_nanos: i * 1_000_000,
+                    to_nanos: (i + 1) * 1_000_000,
+                },
+            })
+            .collect();
+        asupersync::trace::write_trace(&trace, &TraceMetadata::new(1), &events)
+            .expect("write trace");
+
+        let output = dir.join("soak.dict");
+        let args = TrainDictArgs {
+            traces: vec![trace],
+            output: output.clone(),
+            max_size: 2048,
+            max_samples: 0,
+        };
+        cmd_trace_train_dict(&args, true).expect("train dictionary");
+
+        let bytes = fs::read(&output).expect("read dictionary");
+        assert!(!bytes.is_empty() && bytes.len() <= 2048);
+        let dictionary = TraceDictionary::from_bytes(bytes);
+        assert_ne!(dictionary.id(), 0, "trained dictionaries carry a zstd id");
+    }
 }
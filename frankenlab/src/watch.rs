@@ -0,0 +1,557 @@
+//! `frankenlab watch`: re-validate and re-run a scenario whenever it (or any
+//! file it includes) changes.
+//!
+//! The loop is split into small deterministic pieces so it can be tested
+//! without real timers:
+//!
+//! - [`WatchSession`] resolves, validates, and runs the scenario, and diffs
+//!   each run against the previous one.
+//! - [`WatchFingerprint`] captures the content of every watched file.
+//! - [`Debouncer`] turns a stream of fingerprints into "re-run now" signals
+//!   once the files have stopped changing for a quiet window.
+//!
+//! The runtime has no filesystem watcher API, so [`cmd_watch`] polls.
+
+use crate::{LoadedScenario, WatchArgs, load_scenario_with_sources, runner_error_message};
+use asupersync::lab::scenario_runner::{ScenarioRunResult, ScenarioRunner};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// ---------------------------------------------------------------------------
+// Run snapshots and diffs
+// ---------------------------------------------------------------------------
+
+/// The parts of a run that the watch loop compares between iterations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunSnapshot {
+    pub scenario_id: String,
+    pub seed: u64,
+    pub passed: bool,
+    pub steps: u64,
+    pub event_hash: u64,
+    pub schedule_hash: u64,
+    pub trace_fingerprint: u64,
+    /// Checked oracle name -> passed.
+    pub oracles: BTreeMap<String, bool>,
+}
+
+impl RunSnapshot {
+    pub fn from_result(result: &ScenarioRunResult) -> Self {
+        Self {
+            scenario_id: result.scenario_id.clone(),
+            seed: result.seed,
+            passed: result.passed(),
+            steps: result.certificate.steps,
+            event_hash: result.certificate.event_hash,
+            schedule_hash: result.certificate.schedule_hash,
+            trace_fingerprint: result.certificate.trace_fingerprint,
+            oracles: result
+                .oracle_report
+                .entries
+                .iter()
+                .map(|entry| (entry.invariant.clone(), entry.passed))
+                .collect(),
+        }
+    }
+}
+
+/// A single certificate hash that differs between two runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HashChange {
+    pub name: &'static str,
+    pub before: u64,
+    pub after: u64,
+}
+
+/// What changed between two consecutive watch runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RunDiff {
+    pub verdict: Option<(bool, bool)>,
+    pub seed: Option<(u64, u64)>,
+    pub steps_before: u64,
+    pub steps_after: u64,
+    pub newly_failing: Vec<String>,
+    pub newly_passing: Vec<String>,
+    pub oracles_added: Vec<String>,
+    pub oracles_removed: Vec<String>,
+    pub hash_changes: Vec<HashChange>,
+}
+
+impl RunDiff {
+    pub fn between(previous: &RunSnapshot, current: &RunSnapshot) -> Self {
+        let mut diff = Self {
+            verdict: (previous.passed != current.passed)
+                .then_some((previous.passed, current.passed)),
+            seed: (previous.seed != current.seed).then_some((previous.seed, current.seed)),
+            steps_before: previous.steps,
+            steps_after: current.steps,
+            ..Self::default()
+        };
+
+        for (name, &passed) in &current.oracles {
+            match previous.oracles.get(name) {
+                None => diff.oracles_added.push(name.clone()),
+                Some(true) if !passed => diff.newly_failing.push(name.clone()),
+                Some(false) if passed => diff.newly_passing.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.oracles_removed = previous
+            .oracles
+            .keys()
+            .filter(|name| !current.oracles.contains_key(*name))
+            .cloned()
+            .collect();
+
+        for (name, before, after) in [
+            ("event_hash", previous.event_hash, current.event_hash),
+            (
+                "schedule_hash",
+                previous.schedule_hash,
+                current.schedule_hash,
+            ),
+            (
+                "trace_fingerprint",
+                previous.trace_fingerprint,
+                current.trace_fingerprint,
+            ),
+        ] {
+            if before != after {
+                diff.hash_changes.push(HashChange {
+                    name,
+                    before,
+                    after,
+                });
+            }
+        }
+        diff
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.verdict.is_none()
+            && self.seed.is_none()
+            && self.steps_before == self.steps_after
+            && self.newly_failing.is_empty()
+            && self.newly_passing.is_empty()
+            && self.oracles_added.is_empty()
+            && self.oracles_removed.is_empty()
+            && self.hash_changes.is_empty()
+    }
+}
+
+fn verdict_tag(passed: bool) -> &'static str {
+    if passed { "PASS" } else { "FAIL" }
+}
+
+pub fn format_run_diff(diff: &RunDiff) -> String {
+    if diff.is_unchanged() {
+        return "Unchanged from previous run".to_string();
+    }
+    let mut lines = vec!["Changes since previous run:".to_string()];
+    if let Some((before, after)) = diff.verdict {
+        lines.push(format!(
+            "  Verdict: {} -> {}",
+            verdict_tag(before),
+            verdict_tag(after)
+        ));
+    }
+    if let Some((before, after)) = diff.seed {
+        lines.push(format!("  Seed: {before} -> {after}"));
+    }
+    if diff.steps_before != diff.steps_after {
+        let delta = i128::from(diff.steps_after) - i128::from(diff.steps_before);
+        lines.push(format!(
+            "  Steps: {} -> {} ({delta:+})",
+            diff.steps_before, diff.steps_after
+        ));
+    }
+    for name in &diff.newly_failing {
+        lines.push(format!("  Oracle now FAILING: {name}"));
+    }
+    for name in &diff.newly_passing {
+        lines.push(format!("  Oracle now passing: {name}"));
+    }
+    for name in &diff.oracles_added {
+        lines.push(format!("  Oracle added: {name}"));
+    }
+    for name in &diff.oracles_removed {
+        lines.push(format!("  Oracle removed: {name}"));
+    }
+    for change in &diff.hash_changes {
+        lines.push(format!(
+            "  {}: {} -> {}",
+            change.name, change.before, change.after
+        ));
+    }
+    lines.join("\n")
+}
+
+// ---------------------------------------------------------------------------
+// Session
+// ---------------------------------------------------------------------------
+
+/// Result of one watch iteration.
+#[derive(Debug, Clone)]
+pub enum WatchOutcome {
+    /// The scenario (or an include) failed to load or validate; nothing ran.
+    Invalid(Vec<String>),
+    /// The runner rejected the scenario after validation.
+    RunError(String),
+    /// The scenario ran; `diff` is `None` for the first successful run.
+    Ran {
+        snapshot: RunSnapshot,
+        diff: Option<RunDiff>,
+    },
+}
+
+impl WatchOutcome {
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            let report = match self {
+                Self::Invalid(errors) => serde_json::json!({
+                    "status": "invalid",
+                    "errors": errors,
+                }),
+                Self::RunError(error) => serde_json::json!({
+                    "status": "run_error",
+                    "error": error,
+                }),
+                Self::Ran { snapshot, diff } => serde_json::json!({
+                    "status": if snapshot.passed { "pass" } else { "fail" },
+                    "run": snapshot,
+                    "diff": diff,
+                }),
+            };
+            return report.to_string();
+        }
+        match self {
+            Self::Invalid(errors) => {
+                let mut lines = vec!["Scenario is invalid (not re-run):".to_string()];
+                lines.extend(errors.iter().map(|err| format!("  - {err}")));
+                lines.join("\n")
+            }
+            Self::RunError(error) => format!("Run failed: {error}"),
+            Self::Ran { snapshot, diff } => {
+                let passed = snapshot.oracles.values().filter(|&&passed| passed).count();
+                let mut lines = vec![
+                    format!(
+                        "Scenario: {} [{}]",
+                        snapshot.scenario_id,
+                        verdict_tag(snapshot.passed)
+                    ),
+                    format!("Seed: {}", snapshot.seed),
+                    format!("Steps: {}", snapshot.steps),
+                    format!("Oracles: {passed}/{} passed", snapshot.oracles.len()),
+                ];
+                if let Some(diff) = diff {
+                    lines.push(format_run_diff(diff));
+                }
+                lines.join("\n")
+            }
+        }
+    }
+}
+
+/// Content fingerprint of every file the watched scenario depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchFingerprint(Vec<(PathBuf, Option<u64>)>);
+
+/// Watch state that survives between iterations: the files to poll and the
+/// last successful run to diff against.
+#[derive(Debug)]
+pub struct WatchSession {
+    scenario: PathBuf,
+    seed: Option<u64>,
+    sources: Vec<PathBuf>,
+    previous: Option<RunSnapshot>,
+}
+
+impl WatchSession {
+    /// `seed` is the `--seed` override; without it every run uses the
+    /// scenario's own `lab.seed`, so the seed only moves when the file does.
+    pub fn new(scenario: PathBuf, seed: Option<u64>) -> Self {
+        Self {
+            sources: vec![scenario.clone()],
+            scenario,
+            seed,
+            previous: None,
+        }
+    }
+
+    /// Files currently being watched: the scenario and its resolved includes.
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    pub fn fingerprint(&self) -> WatchFingerprint {
+        WatchFingerprint(
+            self.sources
+                .iter()
+                .map(|path| {
+                    let digest = fs::read(path).ok().map(|bytes| {
+                        let mut hasher = DefaultHasher::new();
+                        bytes.hash(&mut hasher);
+                        hasher.finish()
+                    });
+                    (path.clone(), digest)
+                })
+                .collect(),
+        )
+    }
+
+    /// Re-resolves, re-validates, and (only if valid) re-runs the scenario.
+    pub fn evaluate(&mut self) -> WatchOutcome {
+        let LoadedScenario { scenario, sources } = match load_scenario_with_sources(&self.scenario)
+        {
+            Ok(loaded) => loaded,
+            // Keep polling the last known include set so fixing a broken
+            // fragment is noticed.
+            Err(err) => return WatchOutcome::Invalid(vec![err]),
+        };
+        self.sources = sources;
+
+        let errors = scenario.validate();
+        if !errors.is_empty() {
+            return WatchOutcome::Invalid(errors.iter().map(ToString::to_string).collect());
+        }
+
+        match ScenarioRunner::run_with_seed(&scenario, self.seed) {
+            Ok(result) => {
+                let snapshot = RunSnapshot::from_result(&result);
+                let diff = self
+                    .previous
+                    .replace(snapshot.clone())
+                    .map(|previous| RunDiff::between(&previous, &snapshot));
+                WatchOutcome::Ran { snapshot, diff }
+            }
+            Err(err) => WatchOutcome::RunError(runner_error_message(err)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Debounce
+// ---------------------------------------------------------------------------
+
+/// Collapses bursts of saves into one re-run.
+///
+/// A change is reported only after the fingerprint has stayed the same for
+/// `window_ms`; every further change inside the window restarts it.
+#[derive(Debug)]
+pub struct Debouncer {
+    window_ms: u64,
+    settled: Option<WatchFingerprint>,
+    pending: Option<(WatchFingerprint, u64)>,
+}
+
+impl Debouncer {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            settled: None,
+            pending: None,
+        }
+    }
+
+    /// Records the state the last evaluation ran against.
+    pub fn settle(&mut self, fingerprint: WatchFingerprint) {
+        self.settled = Some(fingerprint);
+        self.pending = None;
+    }
+
+    /// Feeds one poll; returns `true` when a quiet, changed state is ready to
+    /// be evaluated.
+    pub fn observe(&mut self, fingerprint: WatchFingerprint, now_ms: u64) -> bool {
+        if self.settled.as_ref() == Some(&fingerprint) {
+            // Reverted to what already ran (or never changed).
+            self.pending = None;
+            return false;
+        }
+        match &self.pending {
+            Some((pending, since))
+                if *pending == fingerprint && now_ms.saturating_sub(*since) >= self.window_ms =>
+            {
+                self.settle(fingerprint);
+                true
+            }
+            Some((pending, _)) if *pending == fingerprint => false,
+            _ => {
+                self.pending = Some((fingerprint, now_ms));
+                false
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Command
+// ---------------------------------------------------------------------------
+
+fn elapsed_ms(start: Instant) -> u64 {
+    u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn cmd_watch(args: WatchArgs, json: bool) -> Result<(), String> {
+    if args.poll_ms == 0 {
+        return Err("--poll-ms must be at least 1".to_string());
+    }
+    let mut session = WatchSession::new(args.scenario.clone(), args.seed);
+    let mut debouncer = Debouncer::new(args.debounce_ms);
+    let start = Instant::now();
+
+    println!("{}", session.evaluate().render(json));
+    debouncer.settle(session.fingerprint());
+    if !json {
+        eprintln!(
+            "Watching {} file(s) for changes (Ctrl-C to stop)",
+            session.sources().len()
+        );
+    }
+
+    loop {
+        std::thread::sleep(Duration::from_millis(args.poll_ms));
+        if debouncer.observe(session.fingerprint(), elapsed_ms(start)) {
+            println!("{}", session.evaluate().render(json));
+            debouncer.settle(session.fingerprint());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_scenario;
+    use crate::tests::scratch_dir;
+    use std::path::Path;
+
+    fn write(path: &Path, contents: &str) {
+        fs::write(path, contents).expect("write scenario file");
+    }
+
+    fn snapshot(oracles: &[(&str, bool)], steps: u64, event_hash: u64) -> RunSnapshot {
+        let oracles: BTreeMap<String, bool> = oracles
+            .iter()
+            .map(|(name, passed)| ((*name).to_string(), *passed))
+            .collect();
+        RunSnapshot {
+            scenario_id: "watched".to_string(),
+            seed: 42,
+            passed: oracles.values().all(|&passed| passed),
+            steps,
+            event_hash,
+            schedule_hash: 7,
+            trace_fingerprint: 9,
+            oracles,
+        }
+    }
+
+    #[test]
+    fn diff_highlights_flipped_oracle_steps_and_hashes() {
+        let before = snapshot(&[("quiescence", true), ("task_leak", true)], 100, 1);
+        let after = snapshot(&[("quiescence", true), ("task_leak", false)], 112, 2);
+
+        let diff = RunDiff::between(&before, &after);
+        assert_eq!(diff.verdict, Some((true, false)));
+        assert_eq!(diff.newly_failing, ["task_leak"]);
+        assert!(diff.newly_passing.is_empty());
+        assert_eq!(diff.hash_changes.len(), 1);
+        assert_eq!(diff.hash_changes[0].name, "event_hash");
+
+        let output = format_run_diff(&diff);
+        assert!(output.contains("Verdict: PASS -> FAIL"));
+        assert!(output.contains("Steps: 100 -> 112 (+12)"));
+        assert!(output.contains("Oracle now FAILING: task_leak"));
+        assert!(output.contains("event_hash: 1 -> 2"));
+        assert!(!output.contains("quiescence"));
+
+        let back = format_run_diff(&RunDiff::between(&after, &before));
+        assert!(back.contains("Oracle now passing: task_leak"));
+        assert!(back.contains("Steps: 112 -> 100 (-12)"));
+
+        assert_eq!(
+            format_run_diff(&RunDiff::between(&after, &after)),
+            "Unchanged from previous run"
+        );
+    }
+
+    #[test]
+    fn rapid_saves_debounce_into_one_rerun() {
+        let dir = scratch_dir("watch_debounce");
+        let path = dir.join("scenario.yaml");
+        write(&path, "id: debounce\n");
+        let session = WatchSession::new(path.clone(), None);
+        let mut debouncer = Debouncer::new(200);
+        debouncer.settle(session.fingerprint());
+
+        assert!(!debouncer.observe(session.fingerprint(), 0));
+
+        // Three saves 50ms apart: each restarts the quiet window.
+        for (i, now) in [(1, 50), (2, 100), (3, 150)] {
+            write(&path, &format!("id: debounce\ndescription: save {i}\n"));
+            assert!(!debouncer.observe(session.fingerprint(), now));
+        }
+        assert!(!debouncer.observe(session.fingerprint(), 300));
+        assert!(debouncer.observe(session.fingerprint(), 350));
+        // Settled: no further re-runs without another change.
+        assert!(!debouncer.observe(session.fingerprint(), 1_000));
+
+        // A save that is reverted inside the window never triggers.
+        let settled = fs::read_to_string(&path).expect("read back");
+        write(&path, "id: debounce\ndescription: oops\n");
+        assert!(!debouncer.observe(session.fingerprint(), 1_050));
+        write(&path, &settled);
+        assert!(!debouncer.observe(session.fingerprint(), 1_100));
+        assert!(!debouncer.observe(session.fingerprint(), 2_000));
+    }
+
+    #[test]
+    fn watch_rerun_matches_cold_run_and_tracks_includes() {
+        let dir = scratch_dir("watch_cold");
+        let fragment = dir.join("lab.yaml");
+        let root = dir.join("scenario.yaml");
+        write(
+            &fragment,
+            "lab:\n  seed: 7\n  worker_count: 1\n  max_steps: 10000\n",
+        );
+        write(
+            &root,
+            "id: watch-cold\ninclude:\n  - path: lab.yaml\nchaos:\n  preset: \"off\"\n",
+        );
+
+        let mut session = WatchSession::new(root.clone(), None);
+        let WatchOutcome::Ran { diff: None, .. } = session.evaluate() else {
+            panic!("first evaluation should run without a diff");
+        };
+        assert_eq!(session.sources().len(), 2);
+
+        // Breaking the fragment is reported without running.
+        write(&fragment, "lab:\n  worker_count: 0\n");
+        let WatchOutcome::Invalid(errors) = session.evaluate() else {
+            panic!("invalid fragment must not run");
+        };
+        assert!(errors.iter().any(|err| err.contains("lab.worker_count")));
+
+        // Fixing it re-runs and diffs against the last good run.
+        write(
+            &fragment,
+            "lab:\n  seed: 8\n  worker_count: 1\n  max_steps: 10000\n",
+        );
+        let WatchOutcome::Ran {
+            snapshot,
+            diff: Some(diff),
+        } = session.evaluate()
+        else {
+            panic!("fixed scenario should run with a diff");
+        };
+        assert_eq!(diff.seed, Some((7, 8)));
+
+        let cold = ScenarioRunner::run_with_seed(&load_scenario(&root).expect("load"), None)
+            .expect("cold run");
+        assert_eq!(snapshot, RunSnapshot::from_result(&cold));
+    }
+}
@@ -0,0 +1,31 @@
+#![no_main]
+//! Differential fuzz target for the channel suite.
+//!
+//! Decodes the input into a `ChannelProgram` (kind, capacity, and per-actor
+//! op scripts), runs it under the lab runtime and the native runtime, and
+//! panics with the full reproduction dump if any execution's canonical
+//! outcome differs from the sequential spec.
+
+use asupersync::lab::channel_differential::{
+    ChannelProgram, ChannelSuite, DifferentialConfig, run_differential,
+};
+use libfuzzer_sys::fuzz_target;
+use std::time::Duration;
+
+fuzz_target!(|data: &[u8]| {
+    let Some(program) = ChannelProgram::from_bytes(data) else {
+        return;
+    };
+    let config = DifferentialConfig {
+        lab_seeds: 2,
+        native_repetitions: 1,
+        native_timeout: Duration::from_secs(5),
+        ..DifferentialConfig::default()
+    };
+    let seed = data
+        .iter()
+        .fold(0u64, |acc, &byte| acc.rotate_left(5) ^ u64::from(byte));
+    if let Err(failure) = run_differential(&ChannelSuite, &program, &config, seed) {
+        panic!("{failure}");
+    }
+});
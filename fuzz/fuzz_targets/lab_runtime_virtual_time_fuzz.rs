@@ -368,6 +368,9 @@ fn verify_virtual_time_report(
         AutoAdvanceTermination::StuckBailout => {
             // Valid - runtime was stuck
         }
+        AutoAdvanceTermination::BlockedByPauses => {
+            return Err("BlockedByPauses without any lab pause".to_string());
+        }
     }
 
     // Steps should be reasonable
@@ -688,7 +688,10 @@ fn test_reject_reason_validity(reason: RejectReason) {
         | RejectReason::InsufficientRank
         | RejectReason::InconsistentEquations
         | RejectReason::InvalidMetadata
-        | RejectReason::MemoryLimitReached => {
+        | RejectReason::MemoryLimitReached
+        | RejectReason::BadPath
+        | RejectReason::UnknownBatch
+        | RejectReason::BatchRootInvalid => {
             // All valid reasons
         }
     }
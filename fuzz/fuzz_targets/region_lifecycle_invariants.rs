@@ -144,6 +144,7 @@ impl From<FuzzRegionLimits> for RegionLimits {
                 .max_children_raw
                 .map(|x| (x as usize).min(MAX_CHILDREN)),
             max_tasks: limits.max_tasks_raw.map(|x| (x as usize).min(MAX_TASKS)),
+            max_live_tasks: None,
             max_obligations: limits
                 .max_obligations_raw
                 .map(|x| (x as usize).min(MAX_OBLIGATIONS)),
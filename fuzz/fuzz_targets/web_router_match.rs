@@ -33,14 +33,10 @@
 //!    in std is randomised per-run), thread-local state, or wall-clock
 //!    time inside the matcher.
 //!
-//! 4. **First-match-wins ordering.** Routes are checked in registration
-//!    order. The fuzzer registers a "before" handler at a specific
-//!    pattern under one status, then either re-registers a different
-//!    handler at the same pattern (must lose to the first) or registers
-//!    a strictly more general pattern after a strictly more specific one
-//!    (specific must still win because it was registered first). The
-//!    asserted invariant is: the response status equals the *earliest*
-//!    matching handler's status — never a later one.
+//! 4. **Duplicate registration is a conflict.** Registering a second
+//!    handler at the same pattern for the same method must be rejected by
+//!    `Router::try_route`, and the first handler keeps answering: the
+//!    response status is the first handler's, never the rejected one's.
 //!
 //! 5. **Catch-all soundness.** A `/*` pattern, when the only registered
 //!    route, MUST match every non-empty path. A nested catch-all under a
@@ -197,12 +193,11 @@ fn catch_all_only() -> &'static Router {
 
 /// Build a fuzz-driven router from up to 4 patterns. Each pattern's status
 /// is set to a distinct value so the oracle can identify which route won.
+///
+/// Patterns that conflict with an earlier accepted one are skipped; a
+/// conflict consumes the router, so each candidate is tried against a fresh
+/// router holding the accepted set.
 fn build_router(seeds: &[u8]) -> Router {
-    let mut router = Router::new().fallback(handler_with_status(StatusCode::NOT_FOUND));
-
-    // First registered pattern wins on ties. Use distinct statuses to
-    // detect ordering bugs — e.g., if the matcher picks the LAST match
-    // instead of the FIRST.
     let statuses = [
         StatusCode::OK,
         StatusCode::CREATED,
@@ -210,17 +205,27 @@ fn build_router(seeds: &[u8]) -> Router {
         StatusCode::NO_CONTENT,
     ];
 
+    let mut accepted = Vec::new();
     for (i, byte) in seeds.iter().take(4).enumerate() {
-        let pattern = shape_pattern(*byte);
-        let status = statuses[i];
-        let mr = match shape_method(*byte) {
+        let candidate = (shape_pattern(*byte), shape_method(*byte), statuses[i]);
+        accepted.push(candidate);
+        if try_build(&accepted).is_none() {
+            accepted.pop();
+        }
+    }
+    try_build(&accepted).expect("accepted routes do not conflict")
+}
+
+fn try_build(routes: &[(&'static str, &'static str, StatusCode)]) -> Option<Router> {
+    let mut router = Router::new().fallback(handler_with_status(StatusCode::NOT_FOUND));
+    for &(pattern, method, status) in routes {
+        let mr = match method {
             "POST" => post(handler_with_status(status)),
             _ => get(handler_with_status(status)),
         };
-        router = router.route(pattern, mr);
+        router = router.try_route(pattern, mr).ok()?;
     }
-
-    router
+    Some(router)
 }
 
 // ---------------------------------------------------------------------------
@@ -278,8 +283,8 @@ fuzz_target!(|data: &[u8]| {
             );
         }
 
-        // Scenario 1: first-match-wins. Register the SAME pattern twice
-        // with distinct statuses; the first registration must answer.
+        // Scenario 1: duplicate registration. Registering the SAME pattern
+        // twice for GET must be rejected; the first registration answers.
         1 => {
             if rest.is_empty() {
                 return;
@@ -290,17 +295,25 @@ fuzz_target!(|data: &[u8]| {
 
             let router = Router::new()
                 .route(pattern, get(handler_with_status(StatusCode::OK)))
-                .route(pattern, get(handler_with_status(StatusCode::CREATED)))
                 .fallback(handler_with_status(StatusCode::NOT_FOUND));
+            let conflict = Router::new()
+                .route(pattern, get(handler_with_status(StatusCode::OK)))
+                .try_route(pattern, get(handler_with_status(StatusCode::CREATED)));
+            assert!(
+                conflict.is_err(),
+                "duplicate GET registration accepted, pattern={pattern:?}"
+            );
 
             let resp = router.handle(Request::new("GET", path));
             assert_well_typed(&resp);
-            // Either the path matches (must be OK, NOT CREATED) or it
-            // doesn't (NOT_FOUND from fallback). CREATED would mean the
-            // second-registered route won — a routing bug.
+            // Either the path matches (OK), doesn't (NOT_FOUND from
+            // fallback), or carries a malformed escape (BAD_REQUEST).
             assert!(
-                matches!(resp.status, StatusCode::OK | StatusCode::NOT_FOUND),
-                "first-match-wins violated: got status {:?}, expected OK or NOT_FOUND, pattern={pattern:?}",
+                matches!(
+                    resp.status,
+                    StatusCode::OK | StatusCode::NOT_FOUND | StatusCode::BAD_REQUEST
+                ),
+                "unexpected status {:?}, pattern={pattern:?}",
                 resp.status
             );
         }
@@ -314,9 +327,11 @@ fuzz_target!(|data: &[u8]| {
             assert_well_typed(&resp);
             // The wildcard `/*` matches when the path has at least one
             // non-empty segment after splitting on `/`. Empty path or
-            // pure-slashes can fall through to the 404 fallback.
+            // pure-slashes can fall through to the 404 fallback, empty
+            // interior segments never match, and a `%` may be a malformed
+            // escape (400).
             let has_segment = path.split('/').any(|s| !s.is_empty());
-            if has_segment {
+            if has_segment && !path.contains("//") && !path.contains('%') {
                 assert_eq!(
                     resp.status,
                     StatusCode::OK,
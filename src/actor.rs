@@ -38,18 +38,67 @@
 //! let result = (&mut handle).join(&cx).await?;
 //! assert_eq!(result.count, 15);
 //! ```
+//!
+//! # Ask
+//!
+//! [`ActorRef::ask`] sends a message carrying a typed [`ReplyTo`] and awaits
+//! the reply. [`ActorRef::ask_stream`] hands the actor a bounded [`ReplySink`]
+//! instead and returns an [`AskStream`] of replies. Both are bounded by the
+//! asker's [`Cx::deadline`].
+//!
+//! ```ignore
+//! enum Query {
+//!     Count(ReplyTo<u64>),
+//!     Sessions(String, ReplySink<Session>),
+//! }
+//!
+//! let count = actor_ref.ask(&cx, Query::Count).await?;
+//! let mut sessions = actor_ref
+//!     .ask_stream(&cx, 16, |sink| Query::Sessions(prefix, sink))
+//!     .await?;
+//! while let Some(session) = sessions.next().await {
+//!     // ...
+//! }
+//! ```
+//!
+//! # Durable sessions
+//!
+//! [`durable`] provides resumable, sequence-numbered message streams between
+//! actors on different nodes that survive restarts of either endpoint.
+//!
+//! # Persistent state
+//!
+//! [`persistent`] rebuilds an actor's state from an event journal on start:
+//! a [`PersistentActor`] spawned inside a [`Persistent`] replies only after
+//! the events a message caused are durable.
+
+pub mod durable;
+pub mod persistent;
+
+pub use durable::{
+    DurableSession, DurableSessionConfig, FileSessionStore, MemorySessionStore, SessionError,
+    SessionFrame, SessionHello, SessionId, SessionJournal, SessionState, SessionStats,
+    SessionStore, SessionStoreError, UnresumableReason,
+};
+pub use persistent::{
+    EventJournal, FileEventJournal, MemoryEventJournal, Persist, PersistenceError, PersistenceId,
+    Persistent, PersistentActor, RecoveryReport,
+};
 
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::task::{Context, Poll};
 use std::time::Duration;
 
-use crate::channel::mpsc;
 use crate::channel::mpsc::SendError;
+use crate::channel::{mpsc, oneshot};
 use crate::cx::Cx;
 use crate::runtime::{JoinError, SpawnError};
-use crate::types::{CxInner, Outcome, RegionId, TaskId, Time};
+use crate::stream::Stream;
+use crate::time::Sleep;
+use crate::types::{CancelReason, CxInner, Outcome, RegionId, TaskId, Time};
 
 /// Unique identifier for an actor.
 ///
@@ -499,6 +548,350 @@ impl<M: Send + 'static> ActorRef<M> {
     }
 }
 
+// ============================================================================
+// Ask Pattern: Typed Replies and Streaming Replies
+// ============================================================================
+
+/// Error returned by [`ActorRef::ask`] and [`ActorRef::ask_stream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AskError {
+    /// The actor has stopped (mailbox closed).
+    ActorStopped,
+    /// The actor dropped the [`ReplyTo`] without replying.
+    NoReply,
+    /// The actor dropped the [`ReplySink`] without calling
+    /// [`finish`](ReplySink::finish).
+    Incomplete,
+    /// The ambient deadline passed before the reply arrived.
+    DeadlineExceeded,
+    /// The asker was cancelled.
+    Cancelled(CancelReason),
+}
+
+impl std::fmt::Display for AskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ActorStopped => write!(f, "actor has stopped"),
+            Self::NoReply => write!(f, "actor did not reply"),
+            Self::Incomplete => write!(f, "actor ended the reply stream without finishing it"),
+            Self::DeadlineExceeded => write!(f, "ask deadline exceeded"),
+            Self::Cancelled(reason) => write!(f, "ask cancelled: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AskError {}
+
+/// Result of replying through a [`ReplyTo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyDelivery {
+    /// The asker received the value.
+    Delivered,
+    /// The asker had already gone (abandoned, timed out or cancelled); the
+    /// value was dropped.
+    AskerGone,
+}
+
+/// Typed single-reply handle embedded in an ask message.
+///
+/// Unlike the gen_server [`Reply`](crate::gen_server::Reply), this is not a
+/// linear obligation: dropping it unreplied surfaces [`AskError::NoReply`] to
+/// the asker, and replying after the asker has gone is suppressed.
+pub struct ReplyTo<R> {
+    sender: oneshot::Sender<R>,
+    deadline: Option<Time>,
+}
+
+impl<R> ReplyTo<R> {
+    /// Sends the reply, consuming the handle.
+    pub fn send(self, value: R) -> ReplyDelivery {
+        match self.sender.send_blocking(value) {
+            Ok(()) => ReplyDelivery::Delivered,
+            Err(_late) => ReplyDelivery::AskerGone,
+        }
+    }
+
+    /// Returns true once the asker has dropped its ask future.
+    #[must_use]
+    pub fn is_abandoned(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Waits until the asker abandons the request.
+    pub async fn abandoned(&mut self) {
+        self.sender.closed().await;
+    }
+
+    /// Returns the asker's deadline, if it had one.
+    #[must_use]
+    pub const fn deadline(&self) -> Option<Time> {
+        self.deadline
+    }
+}
+
+impl<R> std::fmt::Debug for ReplyTo<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplyTo")
+            .field("abandoned", &self.is_abandoned())
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+/// Bounded streaming-reply handle embedded in an [`ActorRef::ask_stream`]
+/// message.
+///
+/// Sends wait for capacity, so a slow asker backpressures the actor instead of
+/// buffering without bound. Call [`finish`](Self::finish) after the last item;
+/// dropping the sink without it ends the asker's stream with
+/// [`AskError::Incomplete`].
+pub struct ReplySink<R> {
+    sender: mpsc::Sender<R>,
+    finished: Arc<AtomicBool>,
+    deadline: Option<Time>,
+}
+
+impl<R> ReplySink<R> {
+    /// Reserves a slot in the reply stream (two-phase send: reserve -> commit).
+    #[must_use]
+    pub fn reserve<'a>(&'a self, cx: &'a Cx) -> mpsc::Reserve<'a, R> {
+        self.sender.reserve(cx)
+    }
+
+    /// Sends one item, waiting for the asker to make room.
+    ///
+    /// Fails with [`SendError::Disconnected`] once the asker has dropped the
+    /// stream.
+    pub async fn send(&self, cx: &Cx, item: R) -> Result<(), SendError<R>> {
+        self.sender.send(cx, item).await
+    }
+
+    /// Marks the stream complete, consuming the sink.
+    pub fn finish(self) {
+        self.finished.store(true, Ordering::Release);
+    }
+
+    /// Returns true once the asker has dropped the stream.
+    #[must_use]
+    pub fn is_abandoned(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Returns the asker's deadline, if it had one.
+    #[must_use]
+    pub const fn deadline(&self) -> Option<Time> {
+        self.deadline
+    }
+}
+
+impl<R> std::fmt::Debug for ReplySink<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplySink")
+            .field("abandoned", &self.is_abandoned())
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Stream of replies returned by [`ActorRef::ask_stream`].
+///
+/// Yields items until the actor finishes, the sink is dropped or the deadline
+/// passes; [`end`](Self::end) then reports which. Dropping the stream closes
+/// it, which the actor observes through [`ReplySink::is_abandoned`].
+#[derive(Debug)]
+pub struct AskStream<R> {
+    receiver: mpsc::Receiver<R>,
+    cx: Cx,
+    finished: Arc<AtomicBool>,
+    deadline: Option<Time>,
+    sleep: Option<Sleep>,
+    end: Option<Result<(), AskError>>,
+}
+
+impl<R> AskStream<R> {
+    /// Returns how the stream ended, or `None` while it is still open.
+    ///
+    /// `Some(Ok(()))` means the actor called [`ReplySink::finish`].
+    #[must_use]
+    pub fn end(&self) -> Option<Result<(), AskError>> {
+        self.end.clone()
+    }
+
+    fn terminate(&mut self, end: Result<(), AskError>) -> Poll<Option<R>> {
+        self.receiver.close();
+        self.end = Some(end);
+        Poll::Ready(None)
+    }
+}
+
+impl<R> Stream for AskStream<R> {
+    type Item = R;
+
+    fn poll_next(self: Pin<&mut Self>, task_cx: &mut Context<'_>) -> Poll<Option<R>> {
+        let this = self.get_mut();
+        if this.end.is_some() {
+            return Poll::Ready(None);
+        }
+
+        match this.receiver.poll_recv(&this.cx, task_cx) {
+            Poll::Ready(Ok(item)) => return Poll::Ready(Some(item)),
+            Poll::Ready(Err(mpsc::RecvError::Disconnected)) => {
+                if this.finished.load(Ordering::Acquire) {
+                    return this.terminate(Ok(()));
+                }
+                this.cx.trace("actor::ask_stream_incomplete");
+                return this.terminate(Err(AskError::Incomplete));
+            }
+            Poll::Ready(Err(mpsc::RecvError::Cancelled)) => {
+                let err = ask_interrupted(&this.cx, this.deadline);
+                return this.terminate(Err(err));
+            }
+            Poll::Ready(Err(mpsc::RecvError::Empty)) | Poll::Pending => {}
+        }
+
+        if let Some(sleep) = this.sleep.as_mut()
+            && Pin::new(sleep).poll(task_cx).is_ready()
+        {
+            this.cx.trace("actor::ask_deadline_exceeded");
+            return this.terminate(Err(AskError::DeadlineExceeded));
+        }
+        Poll::Pending
+    }
+}
+
+impl<M: Send + 'static> ActorRef<M> {
+    /// Sends a request carrying a typed reply handle and waits for the reply.
+    ///
+    /// `make` builds the message around a [`ReplyTo<R>`]. The whole exchange,
+    /// including waiting for mailbox capacity, is bounded by [`Cx::deadline`].
+    /// Dropping the returned future abandons the request; the handler can
+    /// observe this via [`ReplyTo::is_abandoned`].
+    pub async fn ask<R, F>(&self, cx: &Cx, make: F) -> Result<R, AskError>
+    where
+        R: Send + 'static,
+        F: FnOnce(ReplyTo<R>) -> M,
+    {
+        let deadline = cx.deadline();
+        let exchange = async move {
+            self.admit_ask(cx, deadline)?;
+            let (sender, mut receiver) = oneshot::channel();
+            self.enqueue_ask(cx, deadline, make(ReplyTo { sender, deadline }))
+                .await?;
+            match receiver.recv(cx).await {
+                Ok(value) => Ok(value),
+                Err(oneshot::RecvError::Closed) => {
+                    cx.trace("actor::ask_no_reply");
+                    Err(AskError::NoReply)
+                }
+                Err(oneshot::RecvError::Cancelled) => Err(ask_interrupted(cx, deadline)),
+                Err(oneshot::RecvError::PolledAfterCompletion) => {
+                    unreachable!("ask awaits a fresh reply oneshot recv future")
+                }
+            }
+        };
+        within_ask_deadline(cx, deadline, exchange).await
+    }
+
+    /// Sends a request carrying a [`ReplySink<R>`] and returns the reply stream.
+    ///
+    /// At most `capacity` items are buffered between the actor and the asker.
+    /// Enqueueing the request and every later stream poll are bounded by
+    /// [`Cx::deadline`].
+    pub async fn ask_stream<R, F>(
+        &self,
+        cx: &Cx,
+        capacity: usize,
+        make: F,
+    ) -> Result<AskStream<R>, AskError>
+    where
+        R: Send + 'static,
+        F: FnOnce(ReplySink<R>) -> M,
+    {
+        let deadline = cx.deadline();
+        let (sender, receiver) = mpsc::channel(capacity);
+        let finished = Arc::new(AtomicBool::new(false));
+        let sink = ReplySink {
+            sender,
+            finished: Arc::clone(&finished),
+            deadline,
+        };
+        let enqueue = async move {
+            self.admit_ask(cx, deadline)?;
+            self.enqueue_ask(cx, deadline, make(sink)).await
+        };
+        within_ask_deadline(cx, deadline, enqueue).await?;
+        Ok(AskStream {
+            receiver,
+            cx: cx.clone(),
+            finished,
+            deadline,
+            sleep: deadline.map(Sleep::new),
+            end: None,
+        })
+    }
+
+    fn admit_ask(&self, cx: &Cx, deadline: Option<Time>) -> Result<(), AskError> {
+        if cx.checkpoint().is_err() {
+            return Err(ask_interrupted(cx, deadline));
+        }
+        if matches!(
+            self.state.load(),
+            ActorState::Stopping | ActorState::Stopped
+        ) {
+            cx.trace("actor::ask_rejected_stopped");
+            return Err(AskError::ActorStopped);
+        }
+        Ok(())
+    }
+
+    async fn enqueue_ask(&self, cx: &Cx, deadline: Option<Time>, msg: M) -> Result<(), AskError> {
+        match self.sender.send(cx, msg).await {
+            Ok(()) => {
+                cx.trace("actor::ask_enqueued");
+                Ok(())
+            }
+            Err(SendError::Cancelled(_)) => Err(ask_interrupted(cx, deadline)),
+            Err(SendError::Disconnected(_) | SendError::Full(_)) => {
+                cx.trace("actor::ask_send_failed");
+                Err(AskError::ActorStopped)
+            }
+        }
+    }
+}
+
+/// Runs an ask exchange, failing with [`AskError::DeadlineExceeded`] once
+/// `deadline` passes.
+async fn within_ask_deadline<T>(
+    cx: &Cx,
+    deadline: Option<Time>,
+    exchange: impl Future<Output = Result<T, AskError>>,
+) -> Result<T, AskError> {
+    let Some(deadline) = deadline else {
+        return exchange.await;
+    };
+    match crate::time::timeout_at(deadline, std::pin::pin!(exchange)).await {
+        Ok(result) => result,
+        Err(_elapsed) => {
+            cx.trace("actor::ask_deadline_exceeded");
+            Err(AskError::DeadlineExceeded)
+        }
+    }
+}
+
+/// Classifies an interrupted ask: budget deadline exhaustion reports
+/// [`AskError::DeadlineExceeded`], anything else is a cancellation.
+fn ask_interrupted(cx: &Cx, deadline: Option<Time>) -> AskError {
+    if deadline.is_some_and(|deadline| cx.now() >= deadline) {
+        cx.trace("actor::ask_deadline_exceeded");
+        return AskError::DeadlineExceeded;
+    }
+    cx.trace("actor::ask_cancelled");
+    AskError::Cancelled(
+        cx.cancel_reason()
+            .unwrap_or_else(CancelReason::parent_cancelled),
+    )
+}
+
 // ============================================================================
 // ActorContext: Actor-Specific Capability Extension
 // ============================================================================
@@ -1035,7 +1428,6 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
         actor: A,
         mailbox_capacity: usize,
     ) -> Result<(ActorHandle<A>, crate::runtime::stored_task::StoredTask), SpawnError> {
-        use crate::channel::oneshot;
         use crate::cx::scope::CatchUnwind;
         use crate::runtime::stored_task::StoredTask;
         use crate::tracing_compat::{debug, debug_span};
@@ -1148,6 +1540,10 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
     /// ultimately stops or escalates, the original panic payload is still
     /// surfaced as `JoinError::Panicked`.
     ///
+    /// A `panic = "abort"` build cannot restart on panic: the process aborts
+    /// first. Spawning with a restart strategy there logs a warning and traces
+    /// `supervised_actor::restart_on_panic_unavailable`.
+    ///
     /// # Arguments
     ///
     /// * `state` - Runtime state for task creation
@@ -1167,11 +1563,20 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
         A: Actor,
         F: FnMut() -> A + Send + 'static,
     {
-        use crate::channel::oneshot;
         use crate::runtime::stored_task::StoredTask;
-        use crate::supervision::Supervisor;
+        use crate::supervision::{SupervisedActorNode, Supervisor};
         use crate::tracing_compat::{debug, debug_span};
 
+        if matches!(strategy, crate::supervision::SupervisionStrategy::Restart(_))
+            && !crate::runtime::PanicStrategy::current().contains_panics()
+        {
+            crate::tracing_compat::warn!(
+                "supervised actor restart strategy cannot restart on panic: panics abort \
+                 in this build"
+            );
+            cx.trace("supervised_actor::restart_on_panic_unavailable");
+        }
+
         let (msg_tx, msg_rx) = mpsc::channel::<A::Message>(mailbox_capacity);
         let (result_tx, result_rx) = oneshot::channel::<Result<A, JoinError>>();
         let task_id = self.create_task_record(state)?;
@@ -1203,6 +1608,7 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
             mailbox: msg_rx,
             state: Arc::clone(&actor_state),
         };
+        let tree_node = SupervisedActorNode::register(state, region_id, task_id, &strategy);
 
         let wrapped = async move {
             spawn_effects.dispatch();
@@ -1229,6 +1635,7 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
                         child_cx,
                         &mut cell,
                         Supervisor::new(strategy),
+                        &tree_node,
                         task_id,
                         region_id,
                     )
@@ -1286,12 +1693,14 @@ pub enum SupervisedOutcome {
 ///
 /// The mailbox receiver is shared across restarts — messages sent while the
 /// actor is restarting are buffered and processed by the new instance.
+#[allow(clippy::too_many_arguments)]
 async fn run_supervised_loop<A, F>(
     initial_actor: A,
     factory: &mut F,
     cx: Cx,
     cell: &mut ActorCell<A::Message>,
     mut supervisor: crate::supervision::Supervisor,
+    tree_node: &crate::supervision::SupervisedActorNode,
     task_id: TaskId,
     region_id: RegionId,
 ) -> Result<A, JoinError>
@@ -1300,10 +1709,11 @@ where
     F: FnMut() -> A,
 {
     use crate::cx::scope::CatchUnwind;
-    use crate::supervision::SupervisionDecision;
+    use crate::supervision::{ChildRunState, SupervisionDecision};
     use crate::types::Outcome;
 
     let mut current_actor = initial_actor;
+    tree_node.set_state(ChildRunState::Running);
 
     loop {
         // Run the actor until it finishes (normally or via panic)
@@ -1319,6 +1729,7 @@ where
             }
             Err(payload) => {
                 let msg = crate::cx::scope::payload_to_string(&payload);
+                let failure = format!("panicked: {msg}");
                 let panic_payload = crate::types::PanicPayload::new(msg);
                 cx.trace("supervised_actor::failure");
 
@@ -1327,6 +1738,7 @@ where
                 // on_stop is terminal and must not resurrect the actor.
                 if cell.state.load() == ActorState::Stopping || cx.checkpoint().is_err() {
                     cx.trace("supervised_actor::shutdown_panic");
+                    tree_node.record_failure(failure, ChildRunState::Stopped);
                     return Err(JoinError::Panicked(panic_payload));
                 }
 
@@ -1342,6 +1754,7 @@ where
                 match decision {
                     SupervisionDecision::Restart { delay, .. } => {
                         cx.trace("supervised_actor::restart");
+                        tree_node.record_failure(failure, ChildRunState::Restarting);
 
                         // Graceful shutdown may arrive after the crash but
                         // before the delayed restart starts running. That stop
@@ -1378,13 +1791,17 @@ where
                             return Err(JoinError::Panicked(panic_payload));
                         }
                         current_actor = factory();
+                        let restarted_at = Time::from_nanos(supervised_restart_timestamp(&cx));
+                        tree_node.record_restart(restarted_at);
                     }
                     SupervisionDecision::Stop { .. } => {
                         cx.trace("supervised_actor::stopped");
+                        tree_node.record_failure(failure, ChildRunState::Stopped);
                         return Err(JoinError::Panicked(panic_payload));
                     }
                     SupervisionDecision::Escalate { .. } => {
                         cx.trace("supervised_actor::escalated");
+                        tree_node.record_failure(failure, ChildRunState::Stopped);
                         return Err(JoinError::Panicked(panic_payload));
                     }
                 }
@@ -3561,6 +3978,338 @@ mod tests {
         let c3 = c;
         assert_eq!(c3.capacity, c.capacity);
     }
+
+    enum AskMsg {
+        Add(u64, ReplyTo<u64>),
+        Forget(ReplyTo<u64>),
+        Hold(ReplyTo<u64>),
+        WatchAbandon(ReplyTo<u64>),
+        Range(u32, ReplySink<u32>),
+        Partial(u32, ReplySink<u32>),
+    }
+
+    #[derive(Default)]
+    struct AskProbe {
+        produced: std::sync::atomic::AtomicUsize,
+        deliveries: parking_lot::Mutex<Vec<ReplyDelivery>>,
+        deadlines: parking_lot::Mutex<Vec<Option<Time>>>,
+    }
+
+    struct AskActor {
+        total: u64,
+        held: Vec<ReplyTo<u64>>,
+        probe: Arc<AskProbe>,
+    }
+
+    impl AskActor {
+        fn new(probe: &Arc<AskProbe>) -> Self {
+            Self {
+                total: 0,
+                held: Vec::new(),
+                probe: Arc::clone(probe),
+            }
+        }
+
+        async fn stream(&self, cx: &Cx, count: u32, sink: &ReplySink<u32>) -> bool {
+            for item in 0..count {
+                if sink.send(cx, item).await.is_err() {
+                    return false;
+                }
+                self.probe.produced.fetch_add(1, Ordering::SeqCst);
+            }
+            true
+        }
+    }
+
+    impl Actor for AskActor {
+        type Message = AskMsg;
+
+        fn handle(
+            &mut self,
+            cx: &Cx,
+            msg: AskMsg,
+        ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            let cx = cx.clone();
+            Box::pin(async move {
+                match msg {
+                    AskMsg::Add(n, reply) => {
+                        self.total += n;
+                        let delivery = reply.send(self.total);
+                        self.probe.deliveries.lock().push(delivery);
+                    }
+                    AskMsg::Forget(reply) => drop(reply),
+                    AskMsg::Hold(reply) => {
+                        self.probe.deadlines.lock().push(reply.deadline());
+                        self.held.push(reply);
+                    }
+                    AskMsg::WatchAbandon(mut reply) => {
+                        reply.abandoned().await;
+                        let delivery = reply.send(7);
+                        self.probe.deliveries.lock().push(delivery);
+                    }
+                    AskMsg::Range(count, sink) => {
+                        if self.stream(&cx, count, &sink).await {
+                            sink.finish();
+                        }
+                    }
+                    AskMsg::Partial(count, sink) => {
+                        self.stream(&cx, count, &sink).await;
+                        drop(sink);
+                    }
+                }
+            })
+        }
+    }
+
+    fn spawn_ask_actor(
+        runtime: &mut crate::lab::LabRuntime,
+        region: RegionId,
+        probe: &Arc<AskProbe>,
+    ) -> ActorHandle<AskActor> {
+        let cx: Cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let (handle, stored) = scope
+            .spawn_actor(&mut runtime.state, &cx, AskActor::new(probe), 8)
+            .expect("spawn actor");
+        let task_id = handle.task_id();
+        runtime.state.store_spawned_task(task_id, stored);
+        runtime.scheduler.lock().schedule(task_id, 0);
+        handle
+    }
+
+    fn spawn_asker<F>(runtime: &mut crate::lab::LabRuntime, region: RegionId, budget: Budget, f: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(region, budget, f)
+            .expect("create asker");
+        runtime.scheduler.lock().schedule(task_id, 0);
+    }
+
+    #[test]
+    fn ask_round_trips_typed_replies() {
+        init_test("ask_round_trips_typed_replies");
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::default());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let probe = Arc::new(AskProbe::default());
+        let handle = spawn_ask_actor(&mut runtime, region, &probe);
+        let actor_ref = handle.sender();
+        let results = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        let results_for_asker = Arc::clone(&results);
+        spawn_asker(&mut runtime, region, Budget::INFINITE, async move {
+            let cx = Cx::current().expect("lab task context");
+            let first = actor_ref.ask(&cx, |reply| AskMsg::Add(5, reply)).await;
+            let second = actor_ref.ask(&cx, |reply| AskMsg::Add(10, reply)).await;
+            let forgotten = actor_ref.ask(&cx, AskMsg::Forget).await;
+            results_for_asker.lock().extend([first, second, forgotten]);
+        });
+        runtime.run_until_idle();
+
+        let results = results.lock().clone();
+        crate::assert_with_log!(
+            results == vec![Ok(5), Ok(15), Err(AskError::NoReply)],
+            "typed replies",
+            "[Ok(5), Ok(15), Err(NoReply)]",
+            results
+        );
+        let deliveries = probe.deliveries.lock().clone();
+        crate::assert_with_log!(
+            deliveries == vec![ReplyDelivery::Delivered; 2],
+            "replies delivered",
+            "[Delivered, Delivered]",
+            deliveries
+        );
+
+        handle.stop();
+        drop(handle);
+        runtime.run_until_quiescent();
+        crate::test_complete!("ask_round_trips_typed_replies");
+    }
+
+    #[test]
+    fn dropped_ask_is_observable_as_abandonment() {
+        init_test("dropped_ask_is_observable_as_abandonment");
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::default());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let probe = Arc::new(AskProbe::default());
+        let handle = spawn_ask_actor(&mut runtime, region, &probe);
+        let actor_ref = handle.sender();
+
+        spawn_asker(&mut runtime, region, Budget::INFINITE, async move {
+            let cx = Cx::current().expect("lab task context");
+            let mut ask = Box::pin(actor_ref.ask(&cx, AskMsg::WatchAbandon));
+            let pending = std::future::poll_fn(|task_cx| {
+                Poll::Ready(ask.as_mut().poll(task_cx).is_pending())
+            })
+            .await;
+            assert!(pending, "ask must wait for the actor's reply");
+            drop(ask);
+        });
+        runtime.run_until_idle();
+
+        let deliveries = probe.deliveries.lock().clone();
+        crate::assert_with_log!(
+            deliveries == vec![ReplyDelivery::AskerGone],
+            "handler saw abandonment and late reply was suppressed",
+            "[AskerGone]",
+            deliveries
+        );
+
+        handle.stop();
+        drop(handle);
+        runtime.run_until_quiescent();
+        crate::test_complete!("dropped_ask_is_observable_as_abandonment");
+    }
+
+    #[test]
+    fn ask_stream_backpressures_slow_consumer() {
+        init_test("ask_stream_backpressures_slow_consumer");
+        const CAPACITY: usize = 2;
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::default());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let probe = Arc::new(AskProbe::default());
+        let handle = spawn_ask_actor(&mut runtime, region, &probe);
+        let actor_ref = handle.sender();
+        let observed = Arc::new(parking_lot::Mutex::new(None));
+
+        let probe_for_asker = Arc::clone(&probe);
+        let observed_for_asker = Arc::clone(&observed);
+        spawn_asker(&mut runtime, region, Budget::INFINITE, async move {
+            use crate::stream::StreamExt;
+            let cx = Cx::current().expect("lab task context");
+            let mut stream = actor_ref
+                .ask_stream(&cx, CAPACITY, |sink| AskMsg::Range(10, sink))
+                .await
+                .expect("ask_stream");
+            let mut received = Vec::new();
+            let mut max_in_flight = 0;
+            while let Some(item) = stream.next().await {
+                received.push(item);
+                // Slow consumer: give the actor a full virtual millisecond.
+                crate::time::sleep(cx.now(), Duration::from_millis(1)).await;
+                let produced = probe_for_asker.produced.load(Ordering::SeqCst);
+                max_in_flight = max_in_flight.max(produced - received.len());
+            }
+            *observed_for_asker.lock() = Some((received, max_in_flight, stream.end()));
+        });
+        runtime.run_with_auto_advance();
+
+        let (received, max_in_flight, end) = observed.lock().take().expect("asker finished");
+        crate::assert_with_log!(
+            received == (0..10).collect::<Vec<u32>>(),
+            "all items in order",
+            "0..10",
+            received
+        );
+        crate::assert_with_log!(
+            max_in_flight == CAPACITY,
+            "actor blocked at the reply capacity",
+            CAPACITY,
+            max_in_flight
+        );
+        crate::assert_with_log!(end == Some(Ok(())), "finished end", "Some(Ok(()))", end);
+
+        handle.stop();
+        drop(handle);
+        runtime.run_until_quiescent();
+        crate::test_complete!("ask_stream_backpressures_slow_consumer");
+    }
+
+    #[test]
+    fn dropped_reply_sink_ends_stream_incomplete() {
+        init_test("dropped_reply_sink_ends_stream_incomplete");
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::default());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let probe = Arc::new(AskProbe::default());
+        let handle = spawn_ask_actor(&mut runtime, region, &probe);
+        let actor_ref = handle.sender();
+        let observed = Arc::new(parking_lot::Mutex::new(None));
+
+        let observed_for_asker = Arc::clone(&observed);
+        spawn_asker(&mut runtime, region, Budget::INFINITE, async move {
+            use crate::stream::StreamExt;
+            let cx = Cx::current().expect("lab task context");
+            let mut stream = actor_ref
+                .ask_stream(&cx, 4, |sink| AskMsg::Partial(3, sink))
+                .await
+                .expect("ask_stream");
+            let mut received = Vec::new();
+            while let Some(item) = stream.next().await {
+                received.push(item);
+            }
+            *observed_for_asker.lock() = Some((received, stream.end()));
+        });
+        runtime.run_until_idle();
+
+        let (received, end) = observed.lock().take().expect("asker finished");
+        crate::assert_with_log!(
+            received == vec![0, 1, 2],
+            "items before the drop",
+            "[0, 1, 2]",
+            received
+        );
+        crate::assert_with_log!(
+            end == Some(Err(AskError::Incomplete)),
+            "typed incomplete end",
+            "Some(Err(Incomplete))",
+            end
+        );
+
+        handle.stop();
+        drop(handle);
+        runtime.run_until_quiescent();
+        crate::test_complete!("dropped_reply_sink_ends_stream_incomplete");
+    }
+
+    #[test]
+    fn ask_fails_with_deadline_exceeded_at_exact_virtual_time() {
+        init_test("ask_fails_with_deadline_exceeded_at_exact_virtual_time");
+        let deadline = Time::from_millis(50);
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::default());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let probe = Arc::new(AskProbe::default());
+        let handle = spawn_ask_actor(&mut runtime, region, &probe);
+        let actor_ref = handle.sender();
+        let observed = Arc::new(parking_lot::Mutex::new(None));
+
+        let observed_for_asker = Arc::clone(&observed);
+        let budget = Budget::INFINITE.with_deadline(deadline);
+        spawn_asker(&mut runtime, region, budget, async move {
+            let cx = Cx::current().expect("lab task context");
+            let result = actor_ref.ask(&cx, AskMsg::Hold).await;
+            *observed_for_asker.lock() = Some((result, cx.now()));
+        });
+        runtime.run_with_auto_advance();
+
+        let (result, failed_at) = observed.lock().take().expect("asker finished");
+        crate::assert_with_log!(
+            result == Err(AskError::DeadlineExceeded),
+            "deadline error",
+            "Err(DeadlineExceeded)",
+            result
+        );
+        crate::assert_with_log!(
+            failed_at == deadline,
+            "failed exactly at the deadline",
+            deadline,
+            failed_at
+        );
+        let seen = probe.deadlines.lock().clone();
+        crate::assert_with_log!(
+            seen == vec![Some(deadline)],
+            "handler saw the asker deadline",
+            "[Some(50ms)]",
+            seen
+        );
+
+        handle.stop();
+        drop(handle);
+        runtime.run_until_quiescent();
+        crate::test_complete!("ask_fails_with_deadline_exceeded_at_exact_virtual_time");
+    }
 }
 
 // ============================================================================
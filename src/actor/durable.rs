@@ -0,0 +1,944 @@
+//! Resumable, sequence-numbered message streams between actors.
+//!
+//! A [`DurableSession`] is one endpoint of a conversation between two actors
+//! on different nodes that survives restarts of either side. Every message
+//! carries the [`SessionId`] and a sequence number, the receiver acknowledges
+//! cumulatively, and the sender keeps unacknowledged messages in a
+//! [`SessionStore`] until they are acknowledged. After a reconnect the two
+//! endpoints exchange [`SessionHello`]s carrying how far each delivered and
+//! acknowledged, and the sender retransmits exactly what the receiver has not
+//! delivered.
+//!
+//! Like [`crate::distributed::session`], the session is a pure state machine:
+//! the caller moves [`SessionFrame`]s across its transport and supplies the
+//! current time. The transport must deliver frames in order while a
+//! connection lasts (a TCP stream, or the remote module's links); frames in
+//! flight when a connection drops may be lost.
+//!
+//! # Delivery guarantee
+//!
+//! Each inbound message reaches the handler passed to
+//! [`DurableSession::handle_frame`] at most once per session, in sequence
+//! order, and is recorded as delivered before it is acknowledged, so no
+//! acknowledged message is lost. The one gap is a crash after the handler
+//! returns but before [`SessionStore::delivered`] completes: the restarted
+//! endpoint then receives that message again. Handlers whose side effects
+//! must not repeat therefore still need to be idempotent (for example keyed
+//! by the sequence number they are given); the session turns the
+//! at-least-once layer below into exactly-once only up to that window.
+//!
+//! # Resumption
+//!
+//! Each journal has an epoch that changes whenever it is created from
+//! scratch. An endpoint remembers the peer's epoch, so a peer whose store was
+//! lost (or replaced) is detected on the next handshake, as is a peer whose
+//! sequence numbers contradict what was delivered or acknowledged. Either
+//! side then moves to [`SessionState::Unresumable`] and reports
+//! [`SessionError::Unresumable`]; the application resynchronizes in full and
+//! starts over with [`DurableSession::reset`].
+//!
+//! # Flow control
+//!
+//! At most [`DurableSessionConfig::window`] messages may be unacknowledged;
+//! [`DurableSession::send`] fails with [`SessionError::WindowFull`] beyond
+//! that. Receivers acknowledge every
+//! [`ack_every`](DurableSessionConfig::ack_every) deliveries or
+//! [`ack_delay`](DurableSessionConfig::ack_delay) after the first
+//! unacknowledged one, whichever comes first, instead of once per message.
+
+mod store;
+
+pub use store::{
+    DEFAULT_COMPACT_AFTER, FileSessionStore, MemorySessionStore, SessionJournal, SessionStore,
+    SessionStoreError,
+};
+
+use crate::remote::NodeId;
+use crate::types::Time;
+use crate::util::DetHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static EPOCH_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Identifier shared by both endpoints of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SessionId(u64);
+
+impl SessionId {
+    /// Creates a session identifier.
+    #[must_use]
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw identifier.
+    #[must_use]
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "session-{:016x}", self.0)
+    }
+}
+
+/// Window and acknowledgement pacing for a [`DurableSession`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurableSessionConfig {
+    /// Maximum unacknowledged outbound messages.
+    pub window: usize,
+    /// Deliveries after which an acknowledgement is sent immediately.
+    pub ack_every: u64,
+    /// Longest an acknowledgement is held back after a delivery.
+    pub ack_delay: Duration,
+}
+
+impl Default for DurableSessionConfig {
+    fn default() -> Self {
+        Self {
+            window: 64,
+            ack_every: 16,
+            ack_delay: Duration::from_millis(20),
+        }
+    }
+}
+
+/// Resumption handshake sent by each endpoint when a connection opens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionHello {
+    /// Session being resumed.
+    pub session: SessionId,
+    /// Sending endpoint.
+    pub node: NodeId,
+    /// Epoch of the sender's journal.
+    pub epoch: u64,
+    /// Epoch of the receiver's journal as last seen by the sender.
+    pub peer_epoch: Option<u64>,
+    /// Highest inbound sequence number the sender delivered.
+    pub delivered_through: u64,
+    /// Highest outbound sequence number the receiver acknowledged.
+    pub acked_through: u64,
+    /// Sequence number of the sender's next outbound message.
+    pub next_seq: u64,
+}
+
+/// A unit of the session protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionFrame {
+    /// Resumption handshake.
+    Hello(SessionHello),
+    /// An application message.
+    Data {
+        /// Session the message belongs to.
+        session: SessionId,
+        /// Sequence number, starting at 1.
+        seq: u64,
+        /// Message bytes.
+        payload: Vec<u8>,
+    },
+    /// Cumulative acknowledgement.
+    Ack {
+        /// Session being acknowledged.
+        session: SessionId,
+        /// Every message through this sequence number was delivered.
+        through: u64,
+    },
+}
+
+impl SessionFrame {
+    /// Session the frame belongs to.
+    #[must_use]
+    pub const fn session(&self) -> SessionId {
+        match self {
+            Self::Hello(hello) => hello.session,
+            Self::Data { session, .. } | Self::Ack { session, .. } => *session,
+        }
+    }
+}
+
+/// Why a session cannot be resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnresumableReason {
+    /// This endpoint's journal is newer than the one the peer talked to.
+    LocalStateLost,
+    /// The peer's journal is newer than the one this endpoint talked to, or
+    /// its sequence numbers contradict what was delivered or acknowledged.
+    PeerStateLost,
+}
+
+impl fmt::Display for UnresumableReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LocalStateLost => write!(f, "local session state was lost"),
+            Self::PeerStateLost => write!(f, "peer session state was lost"),
+        }
+    }
+}
+
+/// Connection state of a [`DurableSession`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    /// No connection; messages are stored for later.
+    Disconnected,
+    /// Hello sent, waiting for the peer's.
+    Handshaking,
+    /// Frames flow in both directions.
+    Established,
+    /// Resumption failed; [`DurableSession::reset`] starts over.
+    Unresumable(UnresumableReason),
+}
+
+/// Error returned by [`DurableSession`].
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    /// The unacknowledged window is full; wait for acknowledgements.
+    #[error("{window} messages are unacknowledged")]
+    WindowFull {
+        /// Configured window.
+        window: usize,
+    },
+    /// The session cannot be resumed; resynchronize and reset.
+    #[error("session cannot be resumed: {0}")]
+    Unresumable(UnresumableReason),
+    /// The peer sent a frame the protocol does not allow here.
+    #[error("session protocol violation: {0}")]
+    Protocol(String),
+    /// The journal store failed.
+    #[error(transparent)]
+    Store(#[from] SessionStoreError),
+}
+
+/// Counters for one [`DurableSession`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Messages accepted by [`DurableSession::send`].
+    pub sent: u64,
+    /// Messages retransmitted after a handshake.
+    pub retransmitted: u64,
+    /// Messages delivered to the handler.
+    pub delivered: u64,
+    /// Inbound messages dropped as already delivered.
+    pub duplicates: u64,
+    /// Acknowledgements sent.
+    pub acks_sent: u64,
+    /// Acknowledgements received that advanced the window.
+    pub acks_received: u64,
+}
+
+/// One endpoint of a resumable message stream.
+#[derive(Debug)]
+pub struct DurableSession<S: SessionStore> {
+    config: DurableSessionConfig,
+    id: SessionId,
+    local: NodeId,
+    store: S,
+    journal: SessionJournal,
+    state: SessionState,
+    outbox: VecDeque<SessionFrame>,
+    ack_sent_through: u64,
+    ack_due: Option<Time>,
+    stats: SessionStats,
+}
+
+impl<S: SessionStore> DurableSession<S> {
+    /// Opens session `id` for `local`, resuming the journal in `store` or
+    /// creating one.
+    pub fn open(
+        config: DurableSessionConfig,
+        id: SessionId,
+        local: NodeId,
+        mut store: S,
+        now: Time,
+    ) -> Result<Self, SessionError> {
+        let journal = if let Some(journal) = store.load(id)? {
+            journal
+        } else {
+            let epoch = fresh_epoch(&local, now);
+            store.create(id, epoch)?;
+            SessionJournal::new(epoch)
+        };
+        Ok(Self {
+            config,
+            id,
+            local,
+            store,
+            ack_sent_through: journal.delivered_through,
+            journal,
+            state: SessionState::Disconnected,
+            outbox: VecDeque::new(),
+            ack_due: None,
+            stats: SessionStats::default(),
+        })
+    }
+
+    /// Session identifier.
+    #[must_use]
+    pub const fn id(&self) -> SessionId {
+        self.id
+    }
+
+    /// Current state.
+    #[must_use]
+    pub const fn state(&self) -> SessionState {
+        self.state
+    }
+
+    /// Counters since this endpoint was opened.
+    #[must_use]
+    pub const fn stats(&self) -> SessionStats {
+        self.stats
+    }
+
+    /// Outbound messages the peer has not acknowledged.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.journal.unacked.len()
+    }
+
+    /// Highest inbound sequence number delivered to the handler.
+    #[must_use]
+    pub const fn delivered_through(&self) -> u64 {
+        self.journal.delivered_through
+    }
+
+    /// The journal store.
+    #[must_use]
+    pub const fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Closes the session and returns its store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Starts the handshake on a new connection; send the returned frame
+    /// first.
+    pub fn connect(&mut self) -> Result<SessionFrame, SessionError> {
+        self.ensure_resumable()?;
+        self.state = SessionState::Handshaking;
+        self.outbox.clear();
+        // The hello reports everything delivered so far.
+        self.ack_sent_through = self.journal.delivered_through;
+        self.ack_due = None;
+        Ok(SessionFrame::Hello(SessionHello {
+            session: self.id,
+            node: self.local.clone(),
+            epoch: self.journal.epoch,
+            peer_epoch: self.journal.peer_epoch,
+            delivered_through: self.journal.delivered_through,
+            acked_through: self.journal.acked_through,
+            next_seq: self.journal.next_seq,
+        }))
+    }
+
+    /// Records that the connection dropped. Unacknowledged messages are
+    /// retransmitted after the next handshake.
+    pub fn disconnect(&mut self) {
+        if !matches!(self.state, SessionState::Unresumable(_)) {
+            self.state = SessionState::Disconnected;
+        }
+        self.outbox.clear();
+        self.ack_due = None;
+    }
+
+    /// Stores `payload` and queues it for transmission, returning its
+    /// sequence number.
+    ///
+    /// Messages may be sent while disconnected; they go out after the next
+    /// handshake.
+    pub fn send(&mut self, payload: Vec<u8>) -> Result<u64, SessionError> {
+        self.ensure_resumable()?;
+        if self.journal.unacked.len() >= self.config.window {
+            return Err(SessionError::WindowFull {
+                window: self.config.window,
+            });
+        }
+        let seq = self.journal.next_seq;
+        self.store.append(self.id, seq, &payload)?;
+        self.journal.next_seq += 1;
+        self.stats.sent += 1;
+        if self.state == SessionState::Established {
+            self.outbox.push_back(SessionFrame::Data {
+                session: self.id,
+                seq,
+                payload: payload.clone(),
+            });
+        }
+        self.journal.unacked.insert(seq, payload);
+        Ok(seq)
+    }
+
+    /// Processes a frame from the peer, passing each newly delivered message
+    /// to `handler` with its sequence number.
+    pub fn handle_frame<H>(
+        &mut self,
+        frame: SessionFrame,
+        now: Time,
+        mut handler: H,
+    ) -> Result<(), SessionError>
+    where
+        H: FnMut(u64, &[u8]),
+    {
+        self.ensure_resumable()?;
+        if frame.session() != self.id {
+            return Err(SessionError::Protocol(format!(
+                "frame for {} on {}",
+                frame.session(),
+                self.id
+            )));
+        }
+        match frame {
+            SessionFrame::Hello(hello) => self.on_hello(&hello),
+            SessionFrame::Data { seq, payload, .. } => {
+                self.ensure_established("data")?;
+                let delivered = self.journal.delivered_through;
+                if seq <= delivered {
+                    // The peer missed an acknowledgement; repeat it now.
+                    self.stats.duplicates += 1;
+                    self.ack_sent_through = self.ack_sent_through.min(seq.saturating_sub(1));
+                    self.ack_due = Some(now);
+                    return Ok(());
+                }
+                if seq != delivered + 1 {
+                    return Err(SessionError::Protocol(format!(
+                        "sequence {seq} arrived after {delivered}"
+                    )));
+                }
+                handler(seq, &payload);
+                self.store.delivered(self.id, seq)?;
+                self.journal.delivered_through = seq;
+                self.stats.delivered += 1;
+                let delay = u64::try_from(self.config.ack_delay.as_nanos()).unwrap_or(u64::MAX);
+                self.ack_due
+                    .get_or_insert_with(|| now.saturating_add_nanos(delay));
+                Ok(())
+            }
+            SessionFrame::Ack { through, .. } => {
+                self.ensure_established("ack")?;
+                if through >= self.journal.next_seq {
+                    return Err(SessionError::Protocol(format!(
+                        "ack through {through} beyond last sent {}",
+                        self.journal.next_seq - 1
+                    )));
+                }
+                if through > self.journal.acked_through {
+                    self.acknowledge(through)?;
+                    self.stats.acks_received += 1;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Next frame to send, if any: a due acknowledgement first, then
+    /// retransmissions and new messages in sequence order.
+    pub fn poll_transmit(&mut self, now: Time) -> Option<SessionFrame> {
+        if self.state != SessionState::Established {
+            return None;
+        }
+        let delivered = self.journal.delivered_through;
+        let pending = delivered.saturating_sub(self.ack_sent_through);
+        let due = self.ack_due.is_some_and(|due| now >= due);
+        if pending > 0 && (pending >= self.config.ack_every || due) {
+            self.ack_sent_through = delivered;
+            self.ack_due = None;
+            self.stats.acks_sent += 1;
+            return Some(SessionFrame::Ack {
+                session: self.id,
+                through: delivered,
+            });
+        }
+        self.outbox.pop_front()
+    }
+
+    /// When a held-back acknowledgement becomes due; call
+    /// [`poll_transmit`](Self::poll_transmit) then.
+    #[must_use]
+    pub fn poll_deadline(&self) -> Option<Time> {
+        if self.state == SessionState::Established {
+            self.ack_due
+        } else {
+            None
+        }
+    }
+
+    /// Discards all session state and starts a new journal epoch, after the
+    /// application resynchronized an unresumable session.
+    pub fn reset(&mut self, now: Time) -> Result<(), SessionError> {
+        let epoch = fresh_epoch(&self.local, now);
+        self.store.create(self.id, epoch)?;
+        self.journal = SessionJournal::new(epoch);
+        self.state = SessionState::Disconnected;
+        self.outbox.clear();
+        self.ack_sent_through = 0;
+        self.ack_due = None;
+        Ok(())
+    }
+
+    fn on_hello(&mut self, hello: &SessionHello) -> Result<(), SessionError> {
+        if self.state != SessionState::Handshaking {
+            return Err(SessionError::Protocol("hello outside a handshake".to_string()));
+        }
+        if let Err(reason) = self.check_resumable(hello) {
+            crate::tracing_compat::warn!(
+                session = %self.id,
+                peer = %hello.node,
+                reason = %reason,
+                "durable session cannot be resumed"
+            );
+            self.state = SessionState::Unresumable(reason);
+            return Err(SessionError::Unresumable(reason));
+        }
+        if self.journal.peer_epoch != Some(hello.epoch) {
+            self.store.set_peer_epoch(self.id, hello.epoch)?;
+            self.journal.peer_epoch = Some(hello.epoch);
+        }
+        if hello.delivered_through > self.journal.acked_through {
+            self.acknowledge(hello.delivered_through)?;
+        }
+        for (&seq, payload) in &self.journal.unacked {
+            self.outbox.push_back(SessionFrame::Data {
+                session: self.id,
+                seq,
+                payload: payload.clone(),
+            });
+        }
+        self.stats.retransmitted += self.journal.unacked.len() as u64;
+        self.state = SessionState::Established;
+        Ok(())
+    }
+
+    fn check_resumable(&self, hello: &SessionHello) -> Result<(), UnresumableReason> {
+        let journal = &self.journal;
+        if journal.peer_epoch.is_some_and(|epoch| epoch != hello.epoch) {
+            return Err(UnresumableReason::PeerStateLost);
+        }
+        if hello.peer_epoch.is_some_and(|epoch| epoch != journal.epoch) {
+            return Err(UnresumableReason::LocalStateLost);
+        }
+        // Outbound: the peer cannot have delivered what we never sent, nor
+        // forgotten what it acknowledged.
+        if hello.delivered_through >= journal.next_seq {
+            return Err(UnresumableReason::LocalStateLost);
+        }
+        if hello.delivered_through < journal.acked_through {
+            return Err(UnresumableReason::PeerStateLost);
+        }
+        // Inbound: the peer must not reuse sequence numbers we delivered.
+        if hello.next_seq <= journal.delivered_through {
+            return Err(UnresumableReason::PeerStateLost);
+        }
+        if hello.acked_through > journal.delivered_through {
+            return Err(UnresumableReason::LocalStateLost);
+        }
+        Ok(())
+    }
+
+    fn acknowledge(&mut self, through: u64) -> Result<(), SessionError> {
+        self.store.acknowledge(self.id, through)?;
+        self.journal.unacked = self.journal.unacked.split_off(&(through + 1));
+        self.journal.acked_through = through;
+        Ok(())
+    }
+
+    fn ensure_resumable(&self) -> Result<(), SessionError> {
+        match self.state {
+            SessionState::Unresumable(reason) => Err(SessionError::Unresumable(reason)),
+            _ => Ok(()),
+        }
+    }
+
+    fn ensure_established(&self, frame: &str) -> Result<(), SessionError> {
+        if self.state == SessionState::Established {
+            Ok(())
+        } else {
+            Err(SessionError::Protocol(format!(
+                "{frame} before the handshake completed"
+            )))
+        }
+    }
+}
+
+/// A journal epoch that differs across creations, including across
+/// processes that recreate a journal at the same virtual time.
+fn fresh_epoch(local: &NodeId, now: Time) -> u64 {
+    let mut hasher = DetHasher::for_production();
+    local.hash(&mut hasher);
+    now.as_nanos().hash(&mut hasher);
+    EPOCH_COUNTER.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Cx;
+    use crate::lab::LabRuntime;
+    use crate::time::sleep;
+    use crate::types::Budget;
+    use parking_lot::Mutex;
+    use std::future::Future;
+    use std::sync::Arc;
+
+    const SESSION: SessionId = SessionId::new(0x5e55);
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn config(window: usize, ack_every: u64) -> DurableSessionConfig {
+        DurableSessionConfig {
+            window,
+            ack_every,
+            ack_delay: Duration::from_millis(5),
+        }
+    }
+
+    /// An actor's side of the session plus what its handler has seen.
+    struct Endpoint<S: SessionStore> {
+        node: &'static str,
+        config: DurableSessionConfig,
+        session: DurableSession<S>,
+        handled: Vec<u64>,
+    }
+
+    impl<S: SessionStore> Endpoint<S> {
+        fn open(node: &'static str, store: S, config: DurableSessionConfig, now: Time) -> Self {
+            let session = DurableSession::open(config, SESSION, NodeId::new(node), store, now)
+                .expect("open session");
+            Self {
+                node,
+                config,
+                session,
+                handled: Vec::new(),
+            }
+        }
+
+        fn send(&mut self, value: u64) -> Result<u64, SessionError> {
+            self.session.send(value.to_be_bytes().to_vec())
+        }
+
+        fn deliver(&mut self, frame: SessionFrame, now: Time) -> Result<(), SessionError> {
+            let handled = &mut self.handled;
+            self.session.handle_frame(frame, now, |_, payload| {
+                handled.push(u64::from_be_bytes(payload.try_into().expect("u64 payload")));
+            })
+        }
+    }
+
+    impl Endpoint<MemorySessionStore> {
+        /// Process restart: everything but the store and the handler's
+        /// effects is gone.
+        fn restart(&mut self, now: Time) {
+            let store = self.session.store().clone();
+            self.session =
+                DurableSession::open(self.config, SESSION, NodeId::new(self.node), store, now)
+                    .expect("reopen session");
+        }
+    }
+
+    /// Frames in flight in each direction.
+    #[derive(Default)]
+    struct Link {
+        to_a: VecDeque<SessionFrame>,
+        to_b: VecDeque<SessionFrame>,
+    }
+
+    /// Drops the old connection (and whatever was in flight on it) and
+    /// handshakes on a new one.
+    fn reconnect<A: SessionStore, B: SessionStore>(
+        a: &mut Endpoint<A>,
+        b: &mut Endpoint<B>,
+        link: &mut Link,
+    ) {
+        *link = Link::default();
+        a.session.disconnect();
+        b.session.disconnect();
+        link.to_b.push_back(a.session.connect().expect("a hello"));
+        link.to_a.push_back(b.session.connect().expect("b hello"));
+    }
+
+    /// Moves frames both ways until the link is idle or `limit` frames were
+    /// delivered; returns the number delivered.
+    fn pump<A: SessionStore, B: SessionStore>(
+        a: &mut Endpoint<A>,
+        b: &mut Endpoint<B>,
+        link: &mut Link,
+        now: Time,
+        limit: usize,
+    ) -> usize {
+        let mut moved = 0;
+        loop {
+            while let Some(frame) = a.session.poll_transmit(now) {
+                link.to_b.push_back(frame);
+            }
+            while let Some(frame) = b.session.poll_transmit(now) {
+                link.to_a.push_back(frame);
+            }
+            if moved >= limit || (link.to_a.is_empty() && link.to_b.is_empty()) {
+                return moved;
+            }
+            if let Some(frame) = link.to_b.pop_front() {
+                b.deliver(frame, now).expect("b handles frame");
+                moved += 1;
+            }
+            if let Some(frame) = link.to_a.pop_front() {
+                a.deliver(frame, now).expect("a handles frame");
+                moved += 1;
+            }
+        }
+    }
+
+    /// Runs `scenario` on a lab runtime and returns its output.
+    fn in_lab<T, F, Fut>(seed: u64, scenario: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let output = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&output);
+        let mut runtime = LabRuntime::with_seed(seed);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let (task, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("lab cx");
+                let result = scenario(cx).await;
+                *slot.lock() = Some(result);
+            })
+            .expect("create scenario task");
+        runtime.scheduler.lock().schedule(task, 0);
+        let _report = runtime.run_with_auto_advance();
+        let result = output.lock().take();
+        result.expect("scenario finished")
+    }
+
+    #[test]
+    fn restart_of_either_endpoint_loses_and_duplicates_nothing() {
+        init_test("restart_of_either_endpoint_loses_and_duplicates_nothing");
+        let (to_b, to_a, in_flight) = in_lab(7, |cx| async move {
+            let config = config(64, 4);
+            let mut a = Endpoint::open("a", MemorySessionStore::new(), config, cx.now());
+            let mut b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            let mut link = Link::default();
+            reconnect(&mut a, &mut b, &mut link);
+            for value in 1..=10 {
+                a.send(value).expect("a send");
+                b.send(100 + value).expect("b send");
+            }
+            pump(&mut a, &mut b, &mut link, cx.now(), 9);
+
+            // The receiver crashes with data and acks in flight.
+            b.restart(cx.now());
+            reconnect(&mut a, &mut b, &mut link);
+            pump(&mut a, &mut b, &mut link, cx.now(), 7);
+            sleep(cx.now(), Duration::from_millis(2)).await;
+
+            // Then the sender crashes mid-retransmission.
+            a.restart(cx.now());
+            for value in 11..=20 {
+                a.send(value).expect("a send while down");
+            }
+            reconnect(&mut a, &mut b, &mut link);
+            pump(&mut a, &mut b, &mut link, cx.now(), 5);
+            for value in 11..=20 {
+                b.send(100 + value).expect("b send");
+            }
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            sleep(cx.now(), Duration::from_millis(5)).await;
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+
+            let in_flight = (a.session.in_flight(), b.session.in_flight());
+            (b.handled, a.handled, in_flight)
+        });
+
+        let expected: Vec<u64> = (1..=20).collect();
+        crate::assert_with_log!(to_b == expected, "a -> b exactly once", expected, to_b);
+        let expected: Vec<u64> = (101..=120).collect();
+        crate::assert_with_log!(to_a == expected, "b -> a exactly once", expected, to_a);
+        crate::assert_with_log!(in_flight == (0, 0), "all acknowledged", (0, 0), in_flight);
+        crate::test_complete!("restart_of_either_endpoint_loses_and_duplicates_nothing");
+    }
+
+    #[test]
+    fn full_window_pushes_back_until_acknowledged() {
+        init_test("full_window_pushes_back_until_acknowledged");
+        let (blocked, accepted, handled) = in_lab(8, |cx| async move {
+            let config = config(4, 4);
+            let mut a = Endpoint::open("a", MemorySessionStore::new(), config, cx.now());
+            let mut b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            let mut link = Link::default();
+            for value in 1..=4 {
+                a.send(value).expect("within window");
+            }
+            let blocked = matches!(a.send(5), Err(SessionError::WindowFull { window: 4 }));
+            reconnect(&mut a, &mut b, &mut link);
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            let accepted = a.send(5).is_ok();
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            (blocked, accepted, b.handled)
+        });
+
+        crate::assert_with_log!(blocked, "fifth send blocked", true, blocked);
+        crate::assert_with_log!(accepted, "window reopened by ack", true, accepted);
+        let expected = vec![1, 2, 3, 4, 5];
+        crate::assert_with_log!(handled == expected, "delivered", expected, handled);
+        crate::test_complete!("full_window_pushes_back_until_acknowledged");
+    }
+
+    #[test]
+    fn lost_store_surfaces_unresumable_session() {
+        init_test("lost_store_surfaces_unresumable_session");
+        let (errors, send_error, resynced) = in_lab(9, |cx| async move {
+            let config = config(16, 4);
+            let mut a = Endpoint::open("a", MemorySessionStore::new(), config, cx.now());
+            let mut b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            let mut link = Link::default();
+            reconnect(&mut a, &mut b, &mut link);
+            for value in 1..=6 {
+                a.send(value).expect("a send");
+            }
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+
+            // b comes back on a blank disk.
+            sleep(cx.now(), Duration::from_millis(1)).await;
+            b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            a.session.disconnect();
+            let hello_a = a.session.connect().expect("a hello");
+            let hello_b = b.session.connect().expect("b hello");
+            let errors = (
+                a.deliver(hello_b, cx.now()).err().map(|err| err.to_string()),
+                b.deliver(hello_a, cx.now()).err().map(|err| err.to_string()),
+            );
+            let send_error = a.send(7).err().map(|err| err.to_string());
+
+            // The application resynchronizes out of band and starts over.
+            a.session.reset(cx.now()).expect("reset a");
+            b.session.reset(cx.now()).expect("reset b");
+            reconnect(&mut a, &mut b, &mut link);
+            a.send(7).expect("send after reset");
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            (errors, send_error, (b.handled, b.session.delivered_through()))
+        });
+
+        let expected = (
+            Some("session cannot be resumed: peer session state was lost".to_string()),
+            Some("session cannot be resumed: local session state was lost".to_string()),
+        );
+        crate::assert_with_log!(errors == expected, "both sides", expected, errors);
+        let expected = Some("session cannot be resumed: peer session state was lost".to_string());
+        crate::assert_with_log!(send_error == expected, "sticky", expected, send_error);
+        let expected = (vec![7], 1);
+        crate::assert_with_log!(resynced == expected, "fresh sequence", expected, resynced);
+        crate::test_complete!("lost_store_surfaces_unresumable_session");
+    }
+
+    #[test]
+    fn acknowledgements_are_cumulative() {
+        init_test("acknowledgements_are_cumulative");
+        let (acks, repeated) = in_lab(10, |cx| async move {
+            let config = config(64, 16);
+            let mut a = Endpoint::open("a", MemorySessionStore::new(), config, cx.now());
+            let mut b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            let mut link = Link::default();
+            reconnect(&mut a, &mut b, &mut link);
+            for value in 1..=40 {
+                a.send(value).expect("a send");
+            }
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            let before_delay = b.session.stats().acks_sent;
+            sleep(cx.now(), Duration::from_millis(5)).await;
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            let stats = (before_delay, b.session.stats(), a.session.stats());
+
+            // A stale retransmission is dropped and answered right away.
+            let stale = SessionFrame::Data {
+                session: SESSION,
+                seq: 12,
+                payload: 12_u64.to_be_bytes().to_vec(),
+            };
+            b.deliver(stale, cx.now()).expect("duplicate ignored");
+            let repeated = (b.session.poll_transmit(cx.now()), b.handled.len());
+            (stats, repeated)
+        });
+
+        let (before_delay, receiver, sender) = acks;
+        crate::assert_with_log!(before_delay == 2, "every 16 deliveries", 2, before_delay);
+        crate::assert_with_log!(receiver.delivered == 40, "delivered", 40, receiver.delivered);
+        crate::assert_with_log!(receiver.acks_sent == 3, "one per batch", 3, receiver.acks_sent);
+        crate::assert_with_log!(sender.acks_received == 3, "received", 3, sender.acks_received);
+        let expected = (
+            Some(SessionFrame::Ack {
+                session: SESSION,
+                through: 40,
+            }),
+            40,
+        );
+        crate::assert_with_log!(repeated == expected, "duplicate re-acked", expected, repeated);
+        crate::test_complete!("acknowledgements_are_cumulative");
+    }
+
+    #[test]
+    fn file_store_compacts_acknowledged_messages() {
+        init_test("file_store_compacts_acknowledged_messages");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().to_path_buf();
+        let (retained, lines) = in_lab(11, move |cx| async move {
+            let config = config(64, 16);
+            let store = FileSessionStore::new(&path)
+                .expect("file store")
+                .with_compact_after(8);
+            let mut a = Endpoint::open("a", store, config, cx.now());
+            let mut b = Endpoint::open("b", MemorySessionStore::new(), config, cx.now());
+            let mut link = Link::default();
+            reconnect(&mut a, &mut b, &mut link);
+            for value in 1..=20 {
+                a.send(value).expect("a send");
+            }
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            sleep(cx.now(), Duration::from_millis(5)).await;
+            pump(&mut a, &mut b, &mut link, cx.now(), usize::MAX);
+            let journal_path = a.session.store().path(SESSION);
+            let live_lines = std::fs::read_to_string(&journal_path)
+                .expect("journal")
+                .lines()
+                .count();
+            drop(a);
+
+            let mut reopened = FileSessionStore::new(&path).expect("reopen store");
+            let journal = reopened.load(SESSION).expect("load").expect("journal");
+            let reloaded_lines = std::fs::read_to_string(&journal_path)
+                .expect("journal")
+                .lines()
+                .count();
+            (journal, (live_lines, reloaded_lines))
+        });
+
+        let unacked = retained.unacked.len();
+        crate::assert_with_log!(unacked == 0, "nothing retained", 0, unacked);
+        crate::assert_with_log!(retained.acked_through == 20, "acked", 20, retained.acked_through);
+        crate::assert_with_log!(retained.next_seq == 21, "next seq", 21, retained.next_seq);
+        let (live, reloaded) = lines;
+        crate::assert_with_log!(live < 12, "compacted while live", "< 12", live);
+        crate::assert_with_log!(reloaded == 4, "compacted on load", 4, reloaded);
+        crate::test_complete!("file_store_compacts_acknowledged_messages");
+    }
+}
@@ -0,0 +1,425 @@
+//! Journals backing [`DurableSession`](super::DurableSession).
+//!
+//! A journal holds one endpoint's view of one session: the messages it sent
+//! that the peer has not acknowledged, how far the peer acknowledged, and how
+//! far it delivered the peer's messages to its own handler. Stores apply each
+//! update before the session acts on it, so a restarted endpoint resumes from
+//! exactly what it had promised.
+
+use super::SessionId;
+use base64::Engine;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Default number of obsolete records after which a [`FileSessionStore`]
+/// rewrites a journal.
+pub const DEFAULT_COMPACT_AFTER: usize = 128;
+
+/// Error raised by a [`SessionStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum SessionStoreError {
+    /// Reading or writing the journal failed.
+    #[error("session journal I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A journal record could not be parsed.
+    #[error("corrupt session journal at line {line}: {message}")]
+    Corrupt {
+        /// 1-based line number of the bad record.
+        line: usize,
+        /// Parser message.
+        message: String,
+    },
+    /// The session has no journal; it was never created or was discarded.
+    #[error("no journal for {0}")]
+    Missing(SessionId),
+}
+
+/// One endpoint's durable state for one session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionJournal {
+    /// Incarnation of this journal, fresh every time it is created.
+    pub epoch: u64,
+    /// Incarnation of the peer's journal this endpoint last resumed with.
+    pub peer_epoch: Option<u64>,
+    /// Sequence number the next outbound message gets; starts at 1.
+    pub next_seq: u64,
+    /// Highest outbound sequence number the peer acknowledged.
+    pub acked_through: u64,
+    /// Highest inbound sequence number delivered to the handler.
+    pub delivered_through: u64,
+    /// Outbound messages the peer has not acknowledged, by sequence number.
+    pub unacked: BTreeMap<u64, Vec<u8>>,
+}
+
+impl SessionJournal {
+    /// An empty journal with incarnation `epoch`.
+    #[must_use]
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            peer_epoch: None,
+            next_seq: 1,
+            acked_through: 0,
+            delivered_through: 0,
+            unacked: BTreeMap::new(),
+        }
+    }
+
+    fn append(&mut self, seq: u64, payload: Vec<u8>) {
+        self.unacked.insert(seq, payload);
+        self.next_seq = self.next_seq.max(seq.saturating_add(1));
+    }
+
+    /// Drops messages through `through`; returns how many were dropped.
+    fn acknowledge(&mut self, through: u64) -> usize {
+        let before = self.unacked.len();
+        self.unacked = self.unacked.split_off(&through.saturating_add(1));
+        self.acked_through = self.acked_through.max(through);
+        self.next_seq = self.next_seq.max(through.saturating_add(1));
+        before - self.unacked.len()
+    }
+}
+
+/// Durable storage for session journals.
+///
+/// Every call must be durable when it returns: the session sends a message
+/// only after [`append`](Self::append) and acknowledges a delivery only after
+/// [`delivered`](Self::delivered).
+pub trait SessionStore: Send {
+    /// Loads the journal of `session`, if one exists.
+    fn load(&mut self, session: SessionId) -> Result<Option<SessionJournal>, SessionStoreError>;
+
+    /// Creates an empty journal with incarnation `epoch`, replacing any
+    /// existing one.
+    fn create(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError>;
+
+    /// Records the incarnation of the peer's journal.
+    fn set_peer_epoch(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError>;
+
+    /// Records an outbound message.
+    fn append(
+        &mut self,
+        session: SessionId,
+        seq: u64,
+        payload: &[u8],
+    ) -> Result<(), SessionStoreError>;
+
+    /// Records that the peer acknowledged every message through `through`;
+    /// those messages may be discarded.
+    fn acknowledge(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError>;
+
+    /// Records that inbound messages through `through` reached the handler.
+    fn delivered(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError>;
+}
+
+/// Store keeping journals in memory.
+///
+/// Clones share the journals, so an endpoint "restarted" with a clone of its
+/// store resumes; a fresh store behaves like a lost disk.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySessionStore {
+    journals: Arc<Mutex<BTreeMap<SessionId, SessionJournal>>>,
+}
+
+impl MemorySessionStore {
+    /// Creates an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of unacknowledged messages retained for `session`.
+    #[must_use]
+    pub fn retained(&self, session: SessionId) -> usize {
+        self.journals
+            .lock()
+            .get(&session)
+            .map_or(0, |journal| journal.unacked.len())
+    }
+
+    fn update<T>(
+        &self,
+        session: SessionId,
+        update: impl FnOnce(&mut SessionJournal) -> T,
+    ) -> Result<T, SessionStoreError> {
+        let mut journals = self.journals.lock();
+        let journal = journals
+            .get_mut(&session)
+            .ok_or(SessionStoreError::Missing(session))?;
+        Ok(update(journal))
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load(&mut self, session: SessionId) -> Result<Option<SessionJournal>, SessionStoreError> {
+        Ok(self.journals.lock().get(&session).cloned())
+    }
+
+    fn create(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError> {
+        self.journals
+            .lock()
+            .insert(session, SessionJournal::new(epoch));
+        Ok(())
+    }
+
+    fn set_peer_epoch(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError> {
+        self.update(session, |journal| journal.peer_epoch = Some(epoch))
+    }
+
+    fn append(
+        &mut self,
+        session: SessionId,
+        seq: u64,
+        payload: &[u8],
+    ) -> Result<(), SessionStoreError> {
+        self.update(session, |journal| journal.append(seq, payload.to_vec()))
+    }
+
+    fn acknowledge(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError> {
+        self.update(session, |journal| {
+            journal.acknowledge(through);
+        })
+    }
+
+    fn delivered(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError> {
+        self.update(session, |journal| {
+            journal.delivered_through = journal.delivered_through.max(through);
+        })
+    }
+}
+
+/// One line of a journal file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalRecord {
+    Open { epoch: u64 },
+    Peer { epoch: u64 },
+    Send { seq: u64, payload: String },
+    Ack { through: u64 },
+    Delivered { through: u64 },
+}
+
+impl JournalRecord {
+    fn apply(self, journal: &mut Option<SessionJournal>) -> Result<(), String> {
+        let Some(current) = journal.as_mut() else {
+            let Self::Open { epoch } = self else {
+                return Err("record before open".to_string());
+            };
+            *journal = Some(SessionJournal::new(epoch));
+            return Ok(());
+        };
+        match self {
+            Self::Open { epoch } => *current = SessionJournal::new(epoch),
+            Self::Peer { epoch } => current.peer_epoch = Some(epoch),
+            Self::Send { seq, payload } => {
+                let payload = base64::engine::general_purpose::STANDARD
+                    .decode(payload)
+                    .map_err(|err| err.to_string())?;
+                current.append(seq, payload);
+            }
+            Self::Ack { through } => {
+                current.acknowledge(through);
+            }
+            Self::Delivered { through } => {
+                current.delivered_through = current.delivered_through.max(through);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct OpenJournal {
+    file: File,
+    journal: SessionJournal,
+    obsolete: usize,
+}
+
+/// Store keeping one append-only JSON-lines journal file per session.
+///
+/// Every record is synced before the call returns. Acknowledged messages and
+/// superseded delivery marks become obsolete records; once a journal holds
+/// [`compact_after`](Self::with_compact_after) of them it is rewritten
+/// (through a temporary file and a rename) with only the live state. A torn
+/// final record left by a crash is ignored on load.
+#[derive(Debug)]
+pub struct FileSessionStore {
+    dir: PathBuf,
+    compact_after: usize,
+    open: BTreeMap<SessionId, OpenJournal>,
+}
+
+impl FileSessionStore {
+    /// Stores journals in `dir`, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, SessionStoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            compact_after: DEFAULT_COMPACT_AFTER,
+            open: BTreeMap::new(),
+        })
+    }
+
+    /// Rewrites a journal once it holds `records` obsolete records.
+    #[must_use]
+    pub fn with_compact_after(mut self, records: usize) -> Self {
+        self.compact_after = records.max(1);
+        self
+    }
+
+    /// Path of the journal file for `session`.
+    #[must_use]
+    pub fn path(&self, session: SessionId) -> PathBuf {
+        self.dir.join(format!("{session}.journal"))
+    }
+
+    fn replay(path: &Path) -> Result<Option<SessionJournal>, SessionStoreError> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let complete = text.rfind('\n').map_or(0, |end| end + 1);
+        let mut journal = None;
+        for (index, line) in text[..complete].lines().enumerate() {
+            let corrupt = |message: String| SessionStoreError::Corrupt {
+                line: index + 1,
+                message,
+            };
+            let record: JournalRecord =
+                serde_json::from_str(line).map_err(|err| corrupt(err.to_string()))?;
+            record.apply(&mut journal).map_err(corrupt)?;
+        }
+        Ok(journal)
+    }
+
+    /// Writes `journal` as a fresh file at `path` and reopens it for
+    /// appending.
+    fn rewrite(path: &Path, journal: SessionJournal) -> io::Result<OpenJournal> {
+        let staging = path.with_extension("journal.tmp");
+        let mut records = vec![JournalRecord::Open {
+            epoch: journal.epoch,
+        }];
+        if let Some(epoch) = journal.peer_epoch {
+            records.push(JournalRecord::Peer { epoch });
+        }
+        records.push(JournalRecord::Ack {
+            through: journal.acked_through,
+        });
+        records.push(JournalRecord::Delivered {
+            through: journal.delivered_through,
+        });
+        for (&seq, payload) in &journal.unacked {
+            records.push(JournalRecord::Send {
+                seq,
+                payload: base64::engine::general_purpose::STANDARD.encode(payload),
+            });
+        }
+        {
+            let mut file = File::create(&staging)?;
+            for record in &records {
+                write_record(&mut file, record)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&staging, path)?;
+        let file = OpenOptions::new().append(true).open(path)?;
+        Ok(OpenJournal {
+            file,
+            journal,
+            obsolete: 0,
+        })
+    }
+
+    fn journal(&mut self, session: SessionId) -> Result<&mut OpenJournal, SessionStoreError> {
+        let path = self.path(session);
+        match self.open.entry(session) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let journal = Self::replay(&path)?.ok_or(SessionStoreError::Missing(session))?;
+                Ok(entry.insert(Self::rewrite(&path, journal)?))
+            }
+        }
+    }
+
+    fn record(
+        &mut self,
+        session: SessionId,
+        record: &JournalRecord,
+        obsolete: usize,
+    ) -> Result<(), SessionStoreError> {
+        let path = self.path(session);
+        let compact_after = self.compact_after;
+        let open = self.journal(session)?;
+        write_record(&mut open.file, record)?;
+        open.file.sync_data()?;
+        open.obsolete += obsolete;
+        if open.obsolete >= compact_after {
+            *open = Self::rewrite(&path, open.journal.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn load(&mut self, session: SessionId) -> Result<Option<SessionJournal>, SessionStoreError> {
+        self.open.remove(&session);
+        let path = self.path(session);
+        let Some(journal) = Self::replay(&path)? else {
+            return Ok(None);
+        };
+        let open = Self::rewrite(&path, journal.clone())?;
+        self.open.insert(session, open);
+        Ok(Some(journal))
+    }
+
+    fn create(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError> {
+        let open = Self::rewrite(&self.path(session), SessionJournal::new(epoch))?;
+        self.open.insert(session, open);
+        Ok(())
+    }
+
+    fn set_peer_epoch(&mut self, session: SessionId, epoch: u64) -> Result<(), SessionStoreError> {
+        self.journal(session)?.journal.peer_epoch = Some(epoch);
+        self.record(session, &JournalRecord::Peer { epoch }, 0)
+    }
+
+    fn append(
+        &mut self,
+        session: SessionId,
+        seq: u64,
+        payload: &[u8],
+    ) -> Result<(), SessionStoreError> {
+        self.journal(session)?.journal.append(seq, payload.to_vec());
+        let record = JournalRecord::Send {
+            seq,
+            payload: base64::engine::general_purpose::STANDARD.encode(payload),
+        };
+        self.record(session, &record, 0)
+    }
+
+    fn acknowledge(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError> {
+        let dropped = self.journal(session)?.journal.acknowledge(through);
+        // The acknowledged sends and the previous ack record are now dead.
+        self.record(session, &JournalRecord::Ack { through }, dropped + 1)
+    }
+
+    fn delivered(&mut self, session: SessionId, through: u64) -> Result<(), SessionStoreError> {
+        let journal = &mut self.journal(session)?.journal;
+        journal.delivered_through = journal.delivered_through.max(through);
+        self.record(session, &JournalRecord::Delivered { through }, 1)
+    }
+}
+
+fn write_record(file: &mut File, record: &JournalRecord) -> io::Result<()> {
+    let mut line = serde_json::to_vec(record).map_err(io::Error::other)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
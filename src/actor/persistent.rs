@@ -0,0 +1,872 @@
+//! Event-sourced actor state that survives restarts.
+//!
+//! A [`PersistentActor`] never mutates its state while handling a message.
+//! It decides which events the message causes and returns them in a
+//! [`Persist`]; the [`Persistent`] adapter, which is the [`Actor`] actually
+//! spawned, appends them to an [`EventJournal`] and only then applies them
+//! through [`PersistentActor::apply_event`] and runs the follow-up attached
+//! with [`Persist::then`]. Replies sent from that follow-up therefore never
+//! announce state the journal does not hold: the append is the commit point.
+//! If it fails, the events are not applied, the follow-up is dropped (so a
+//! [`ReplyTo`](super::ReplyTo) inside it reports the actor gone), and the
+//! actor halts until it is restarted and recovers what the journal really
+//! contains.
+//!
+//! # Recovery
+//!
+//! Recovery runs in [`Actor::on_start`]: the latest snapshot is restored and
+//! the events after it are replayed before the first message is handled.
+//! Messages sent meanwhile wait in the mailbox and are handled afterwards in
+//! order. Every [`snapshot_every`](Persistent::with_snapshot_every) events
+//! the adapter saves a snapshot, and the journal discards the events it
+//! covers, which bounds recovery time.
+//!
+//! # Schema evolution
+//!
+//! Events and snapshots are stored as JSON tagged with
+//! [`EVENT_VERSION`](PersistentActor::EVENT_VERSION) and
+//! [`SNAPSHOT_VERSION`](PersistentActor::SNAPSHOT_VERSION). When a handler's
+//! event type changes, bump the version and implement
+//! [`upcast_event`](PersistentActor::upcast_event), which lifts a stored
+//! event one version at a time; recovery applies it until the event reaches
+//! the current version. Records written by a newer version fail recovery
+//! rather than being misread.
+//!
+//! # Example
+//!
+//! ```ignore
+//! impl PersistentActor for Account {
+//!     type Message = Command;
+//!     type Event = Posted;
+//!     type Snapshot = i64;
+//!
+//!     fn persistence_id(&self) -> PersistenceId {
+//!         PersistenceId::new(format!("account/{}", self.number))
+//!     }
+//!
+//!     fn handle(&self, _cx: &Cx, msg: Command) -> Persist<Self> {
+//!         match msg {
+//!             Command::Deposit(amount, reply) => Persist::event(Posted(amount))
+//!                 .then(move |account| {
+//!                     reply.send(account.balance);
+//!                 }),
+//!         }
+//!     }
+//!
+//!     fn apply_event(&mut self, Posted(amount): Posted) {
+//!         self.balance += amount;
+//!     }
+//!
+//!     fn snapshot(&self) -> i64 {
+//!         self.balance
+//!     }
+//!
+//!     fn restore(&mut self, balance: i64) {
+//!         self.balance = balance;
+//!     }
+//! }
+//!
+//! let journal = FileEventJournal::new("/var/lib/app/accounts")?;
+//! let (handle, stored) =
+//!     scope.spawn_actor(&mut state, &cx, Persistent::new(account, journal), 32)?;
+//! ```
+
+mod journal;
+
+pub use journal::{
+    EventJournal, FileEventJournal, JournalEvent, JournalSnapshot, LoadedJournal,
+    MemoryEventJournal, PersistenceError, PersistenceId,
+};
+
+use super::Actor;
+use crate::cx::Cx;
+use crate::tracing_compat::{error, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Default number of events between snapshots.
+pub const DEFAULT_SNAPSHOT_EVERY: u64 = 256;
+
+/// An actor whose state is rebuilt from persisted events.
+///
+/// Spawn it wrapped in a [`Persistent`].
+pub trait PersistentActor: Send + Sized + 'static {
+    /// The type of messages this actor can receive.
+    type Message: Send + 'static;
+    /// State changes, as persisted.
+    type Event: Serialize + DeserializeOwned + Send + 'static;
+    /// Persisted form of the whole state.
+    type Snapshot: Serialize + DeserializeOwned;
+
+    /// Schema version written with new events.
+    const EVENT_VERSION: u32 = 1;
+    /// Schema version written with new snapshots.
+    const SNAPSHOT_VERSION: u32 = 1;
+
+    /// Journal identity; must be stable across restarts.
+    fn persistence_id(&self) -> PersistenceId;
+
+    /// Decides what `msg` changes. The state is only read here.
+    fn handle(&self, cx: &Cx, msg: Self::Message) -> Persist<Self>;
+
+    /// Applies one event to the state, during handling and during recovery.
+    fn apply_event(&mut self, event: Self::Event);
+
+    /// Captures the whole state.
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Replaces the state with a captured one.
+    fn restore(&mut self, snapshot: Self::Snapshot);
+
+    /// Lifts an event stored at `version` to `version + 1`.
+    ///
+    /// The default accepts no old versions.
+    fn upcast_event(version: u32, _event: serde_json::Value) -> Result<serde_json::Value, String> {
+        Err(format!("no event upcaster from version {version}"))
+    }
+
+    /// Lifts a snapshot stored at `version` to `version + 1`.
+    ///
+    /// The default accepts no old versions.
+    fn upcast_snapshot(
+        version: u32,
+        _snapshot: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        Err(format!("no snapshot upcaster from version {version}"))
+    }
+}
+
+type FollowUp<A> = Box<dyn FnOnce(&A) + Send>;
+
+/// What handling one message persists, and what to do once it is durable.
+#[must_use = "a Persist does nothing unless returned from a handler"]
+pub struct Persist<A: PersistentActor> {
+    events: Vec<A::Event>,
+    then: Option<FollowUp<A>>,
+}
+
+impl<A: PersistentActor> Persist<A> {
+    /// Persists nothing.
+    pub fn nothing() -> Self {
+        Self {
+            events: Vec::new(),
+            then: None,
+        }
+    }
+
+    /// Persists one event.
+    pub fn event(event: A::Event) -> Self {
+        Self::events([event])
+    }
+
+    /// Persists `events` as one atomic batch.
+    pub fn events(events: impl IntoIterator<Item = A::Event>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+            then: None,
+        }
+    }
+
+    /// Runs `then` with the updated state once the events are durable and
+    /// applied. It is dropped without running if persisting fails.
+    pub fn then(mut self, then: impl FnOnce(&A) + Send + 'static) -> Self {
+        self.then = Some(Box::new(then));
+        self
+    }
+}
+
+impl<A: PersistentActor> fmt::Debug for Persist<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Persist")
+            .field("events", &self.events.len())
+            .field("then", &self.then.is_some())
+            .finish()
+    }
+}
+
+/// What recovery found in the journal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Sequence number of the restored snapshot, if there was one.
+    pub snapshot_seq: Option<u64>,
+    /// Events replayed on top of the snapshot.
+    pub replayed: usize,
+    /// Records that needed upcasting.
+    pub upcast: usize,
+    /// Damaged or torn records the journal dropped.
+    pub discarded: usize,
+}
+
+/// Runs a [`PersistentActor`] as an [`Actor`], persisting its events.
+pub struct Persistent<P: PersistentActor> {
+    actor: P,
+    id: PersistenceId,
+    journal: Box<dyn EventJournal>,
+    snapshot_every: Option<u64>,
+    seq: u64,
+    snapshot_seq: u64,
+    recovery: Option<RecoveryReport>,
+    halted: bool,
+}
+
+impl<P: PersistentActor> Persistent<P> {
+    /// Wraps `actor`, which should hold its initial (empty) state, with
+    /// `journal`.
+    pub fn new(actor: P, journal: impl EventJournal + 'static) -> Self {
+        let id = actor.persistence_id();
+        Self {
+            actor,
+            id,
+            journal: Box::new(journal),
+            snapshot_every: Some(DEFAULT_SNAPSHOT_EVERY),
+            seq: 0,
+            snapshot_seq: 0,
+            recovery: None,
+            halted: false,
+        }
+    }
+
+    /// Saves a snapshot every `events` persisted events; `0` disables
+    /// snapshots.
+    #[must_use]
+    pub fn with_snapshot_every(mut self, events: u64) -> Self {
+        self.snapshot_every = (events > 0).then_some(events);
+        self
+    }
+
+    /// Returns the journal identity.
+    #[must_use]
+    pub fn persistence_id(&self) -> &PersistenceId {
+        &self.id
+    }
+
+    /// Returns the wrapped actor.
+    #[must_use]
+    pub fn actor(&self) -> &P {
+        &self.actor
+    }
+
+    /// Unwraps the actor.
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.actor
+    }
+
+    /// Sequence number of the last event applied.
+    #[must_use]
+    pub fn last_seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Returns the recovery outcome once recovery succeeded.
+    #[must_use]
+    pub fn recovery(&self) -> Option<RecoveryReport> {
+        self.recovery
+    }
+
+    /// Returns true once a journal failure stopped the actor persisting.
+    #[must_use]
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Restores the latest snapshot and replays the events after it.
+    ///
+    /// Runs from [`Actor::on_start`]; later calls return the first report.
+    /// A failure halts the actor.
+    pub fn recover(&mut self) -> Result<RecoveryReport, PersistenceError> {
+        if let Some(report) = self.recovery {
+            return Ok(report);
+        }
+        if self.halted {
+            return Err(PersistenceError::Halted(self.id.clone()));
+        }
+        match self.replay() {
+            Ok(report) => {
+                self.recovery = Some(report);
+                Ok(report)
+            }
+            Err(err) => {
+                self.halted = true;
+                Err(err)
+            }
+        }
+    }
+
+    /// Handles `msg`: persists its events, applies them, then runs its
+    /// follow-up.
+    ///
+    /// Runs from [`Actor::handle`]. A journal failure halts the actor.
+    pub fn process(&mut self, cx: &Cx, msg: P::Message) -> Result<(), PersistenceError> {
+        if self.halted {
+            return Err(PersistenceError::Halted(self.id.clone()));
+        }
+        if self.recovery.is_none() {
+            return Err(PersistenceError::NotRecovered(self.id.clone()));
+        }
+        let Persist { events, then } = self.actor.handle(cx, msg);
+        if !events.is_empty() {
+            let mut records = Vec::with_capacity(events.len());
+            let mut seq = self.seq;
+            for event in &events {
+                seq += 1;
+                records.push(JournalEvent {
+                    seq,
+                    version: P::EVENT_VERSION,
+                    payload: serde_json::to_value(event).map_err(|err| self.codec(&err))?,
+                });
+            }
+            if let Err(err) = self.journal.append(&self.id, &records) {
+                self.halted = true;
+                return Err(err);
+            }
+            for event in events {
+                self.actor.apply_event(event);
+            }
+            self.seq = seq;
+        }
+        if let Some(then) = then {
+            then(&self.actor);
+        }
+        self.maybe_snapshot();
+        Ok(())
+    }
+
+    fn replay(&mut self) -> Result<RecoveryReport, PersistenceError> {
+        let loaded = self.journal.load(&self.id)?;
+        let mut report = RecoveryReport {
+            discarded: loaded.discarded,
+            ..RecoveryReport::default()
+        };
+        if let Some(snapshot) = loaded.snapshot {
+            if snapshot.version != P::SNAPSHOT_VERSION {
+                report.upcast += 1;
+            }
+            let payload = upcast(
+                snapshot.version,
+                P::SNAPSHOT_VERSION,
+                snapshot.payload,
+                P::upcast_snapshot,
+            )
+            .map_err(|message| self.upcast_error(snapshot.seq, snapshot.version, message))?;
+            let state = serde_json::from_value(payload).map_err(|err| self.codec(&err))?;
+            self.actor.restore(state);
+            self.seq = snapshot.seq;
+            self.snapshot_seq = snapshot.seq;
+            report.snapshot_seq = Some(snapshot.seq);
+        }
+        for event in loaded.events {
+            if event.seq <= self.seq {
+                continue;
+            }
+            if event.seq != self.seq + 1 {
+                return Err(PersistenceError::Corrupt {
+                    id: self.id.clone(),
+                    record: report.replayed + 1,
+                    message: format!("expected seq {}, found {}", self.seq + 1, event.seq),
+                });
+            }
+            if event.version != P::EVENT_VERSION {
+                report.upcast += 1;
+            }
+            let payload = upcast(event.version, P::EVENT_VERSION, event.payload, P::upcast_event)
+                .map_err(|message| self.upcast_error(event.seq, event.version, message))?;
+            let decoded = serde_json::from_value(payload).map_err(|err| self.codec(&err))?;
+            self.actor.apply_event(decoded);
+            self.seq = event.seq;
+            report.replayed += 1;
+        }
+        Ok(report)
+    }
+
+    /// Saves a snapshot once enough events accumulated; a failed snapshot
+    /// only costs recovery time, so it is logged and retried later.
+    fn maybe_snapshot(&mut self) {
+        let Some(every) = self.snapshot_every else {
+            return;
+        };
+        if self.seq - self.snapshot_seq < every {
+            return;
+        }
+        let snapshot = match serde_json::to_value(self.actor.snapshot()) {
+            Ok(payload) => JournalSnapshot {
+                seq: self.seq,
+                version: P::SNAPSHOT_VERSION,
+                payload,
+            },
+            Err(_err) => {
+                warn!(journal = %self.id, error = %_err, "cannot encode actor snapshot");
+                return;
+            }
+        };
+        match self.journal.save_snapshot(&self.id, snapshot) {
+            Ok(()) => self.snapshot_seq = self.seq,
+            Err(_err) => warn!(journal = %self.id, error = %_err, "actor snapshot failed"),
+        }
+    }
+
+    fn codec(&self, err: &serde_json::Error) -> PersistenceError {
+        PersistenceError::Codec {
+            id: self.id.clone(),
+            message: err.to_string(),
+        }
+    }
+
+    fn upcast_error(&self, seq: u64, version: u32, message: String) -> PersistenceError {
+        PersistenceError::Upcast {
+            id: self.id.clone(),
+            seq,
+            version,
+            message,
+        }
+    }
+}
+
+impl<P: PersistentActor> fmt::Debug for Persistent<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Persistent")
+            .field("id", &self.id)
+            .field("seq", &self.seq)
+            .field("snapshot_seq", &self.snapshot_seq)
+            .field("recovery", &self.recovery)
+            .field("halted", &self.halted)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: PersistentActor> Actor for Persistent<P> {
+    type Message = P::Message;
+
+    fn on_start(&mut self, _cx: &Cx) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        if let Err(_err) = self.recover() {
+            error!(journal = %self.id, error = %_err, "actor recovery failed; actor halted");
+        }
+        Box::pin(async {})
+    }
+
+    fn handle(
+        &mut self,
+        cx: &Cx,
+        msg: P::Message,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        if let Err(_err) = self.process(cx, msg) {
+            warn!(journal = %self.id, error = %_err, "persistent actor dropped a message");
+        }
+        Box::pin(async {})
+    }
+}
+
+/// Applies `step` until `payload` is at version `current`.
+fn upcast(
+    mut version: u32,
+    current: u32,
+    mut payload: serde_json::Value,
+    step: fn(u32, serde_json::Value) -> Result<serde_json::Value, String>,
+) -> Result<serde_json::Value, String> {
+    if version > current {
+        return Err(format!("written by newer version {version} (current {current})"));
+    }
+    while version < current {
+        payload = step(version, payload)?;
+        version += 1;
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Scope;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::types::Budget;
+    use crate::types::policy::FailFast;
+    use parking_lot::Mutex;
+    use serde::Deserialize;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    enum Command {
+        Deposit(i64, Log),
+        Withdraw(i64, Log),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Posted {
+        Credit { cents: i64 },
+        Debit { cents: i64 },
+    }
+
+    #[derive(Debug, Default)]
+    struct Account {
+        name: &'static str,
+        balance: i64,
+        postings: u64,
+    }
+
+    impl Account {
+        fn named(name: &'static str) -> Self {
+            Self {
+                name,
+                ..Self::default()
+            }
+        }
+    }
+
+    impl PersistentActor for Account {
+        type Message = Command;
+        type Event = Posted;
+        type Snapshot = (i64, u64);
+
+        const EVENT_VERSION: u32 = 2;
+
+        fn persistence_id(&self) -> PersistenceId {
+            PersistenceId::new(format!("account/{}", self.name))
+        }
+
+        fn handle(&self, _cx: &Cx, msg: Command) -> Persist<Self> {
+            let (persist, log, cents) = match msg {
+                Command::Deposit(cents, log) => {
+                    (Persist::event(Posted::Credit { cents }), log, cents)
+                }
+                Command::Withdraw(cents, log) if cents > self.balance => {
+                    log.lock().push(format!("rejected {cents}"));
+                    return Persist::nothing();
+                }
+                Command::Withdraw(cents, log) => {
+                    (Persist::event(Posted::Debit { cents }), log, cents)
+                }
+            };
+            persist.then(move |account: &Self| {
+                log.lock()
+                    .push(format!("reply {cents} -> {}", account.balance));
+            })
+        }
+
+        fn apply_event(&mut self, event: Posted) {
+            match event {
+                Posted::Credit { cents } => self.balance += cents,
+                Posted::Debit { cents } => self.balance -= cents,
+            }
+            self.postings += 1;
+        }
+
+        fn snapshot(&self) -> (i64, u64) {
+            (self.balance, self.postings)
+        }
+
+        fn restore(&mut self, (balance, postings): (i64, u64)) {
+            self.balance = balance;
+            self.postings = postings;
+        }
+
+        /// Version 1 stored signed amounts: `{"amount": -5}`.
+        fn upcast_event(
+            version: u32,
+            event: serde_json::Value,
+        ) -> Result<serde_json::Value, String> {
+            if version != 1 {
+                return Err(format!("unknown version {version}"));
+            }
+            let amount = event["amount"].as_i64().ok_or("missing amount")?;
+            let posted = if amount >= 0 {
+                Posted::Credit { cents: amount }
+            } else {
+                Posted::Debit { cents: -amount }
+            };
+            serde_json::to_value(posted).map_err(|err| err.to_string())
+        }
+    }
+
+    /// Journal wrapper recording appends into the same log as the replies.
+    struct RecordingJournal {
+        inner: MemoryEventJournal,
+        log: Log,
+        fail_appends: bool,
+    }
+
+    impl EventJournal for RecordingJournal {
+        fn load(&mut self, id: &PersistenceId) -> Result<LoadedJournal, PersistenceError> {
+            self.inner.load(id)
+        }
+
+        fn append(
+            &mut self,
+            id: &PersistenceId,
+            events: &[JournalEvent],
+        ) -> Result<(), PersistenceError> {
+            if self.fail_appends {
+                return Err(std::io::Error::other("disk gone").into());
+            }
+            self.inner.append(id, events)?;
+            self.log.lock().push(format!("append {}", events[0].seq));
+            Ok(())
+        }
+
+        fn save_snapshot(
+            &mut self,
+            id: &PersistenceId,
+            snapshot: JournalSnapshot,
+        ) -> Result<(), PersistenceError> {
+            self.inner.save_snapshot(id, snapshot)
+        }
+    }
+
+    fn run(account: &mut Persistent<Account>, commands: impl IntoIterator<Item = Command>) {
+        let cx = Cx::for_testing();
+        for command in commands {
+            account.process(&cx, command).expect("process");
+        }
+    }
+
+    fn log() -> Log {
+        Arc::new(Mutex::new(Vec::new()))
+    }
+
+    #[test]
+    fn crash_restart_recovers_exact_state() {
+        init_test("crash_restart_recovers_exact_state");
+        let journal = MemoryEventJournal::new();
+        let log = log();
+
+        let mut first = Persistent::new(Account::named("a"), journal.clone());
+        first.recover().expect("fresh recovery");
+        run(
+            &mut first,
+            [
+                Command::Deposit(100, log.clone()),
+                Command::Withdraw(30, log.clone()),
+                Command::Withdraw(500, log.clone()),
+                Command::Deposit(7, log.clone()),
+            ],
+        );
+        let before = first.actor().snapshot();
+        // Crash: the in-memory actor is lost without any shutdown step.
+        drop(first);
+
+        let mut second = Persistent::new(Account::named("a"), journal.clone());
+        let report = second.recover().expect("recovery");
+        crate::assert_with_log!(
+            second.actor().snapshot() == before,
+            "recovered state",
+            before,
+            second.actor().snapshot()
+        );
+        crate::assert_with_log!(report.replayed == 3, "replayed", 3, report.replayed);
+        assert_eq!(second.last_seq(), 3);
+
+        // Journals are isolated by persistence id.
+        let mut other = Persistent::new(Account::named("b"), journal);
+        other.recover().expect("other recovery");
+        assert_eq!(other.actor().balance, 0);
+        crate::test_complete!("crash_restart_recovers_exact_state");
+    }
+
+    #[test]
+    fn snapshot_plus_tail_replay_and_compaction() {
+        init_test("snapshot_plus_tail_replay_and_compaction");
+        let journal = MemoryEventJournal::new();
+        let id = PersistenceId::new("account/s");
+        let log = log();
+
+        let mut first = Persistent::new(Account::named("s"), journal.clone())
+            .with_snapshot_every(4);
+        first.recover().expect("fresh recovery");
+        run(&mut first, (1..=6).map(|cents| Command::Deposit(cents, log.clone())));
+        // The snapshot at seq 4 compacted events 1..=4 away.
+        crate::assert_with_log!(journal.retained(&id) == 2, "retained", 2, journal.retained(&id));
+        drop(first);
+
+        let mut second = Persistent::new(Account::named("s"), journal)
+            .with_snapshot_every(4);
+        let report = second.recover().expect("recovery");
+        assert_eq!(report.snapshot_seq, Some(4));
+        assert_eq!(report.replayed, 2);
+        assert_eq!(second.actor().snapshot(), (21, 6));
+        crate::test_complete!("snapshot_plus_tail_replay_and_compaction");
+    }
+
+    #[test]
+    fn upcaster_applies_to_old_events() {
+        init_test("upcaster_applies_to_old_events");
+        let mut journal = MemoryEventJournal::new();
+        let id = PersistenceId::new("account/old");
+        let amounts = [serde_json::json!({ "amount": 50 }), serde_json::json!({ "amount": -8 })];
+        let old: Vec<_> = amounts
+            .into_iter()
+            .zip(1..)
+            .map(|(payload, seq)| JournalEvent {
+                seq,
+                version: 1,
+                payload,
+            })
+            .collect();
+        journal.append(&id, &old).expect("seed old events");
+
+        let mut account = Persistent::new(Account::named("old"), journal.clone());
+        let report = account.recover().expect("recovery");
+        assert_eq!(report.upcast, 2);
+        assert_eq!(account.actor().snapshot(), (42, 2));
+
+        // New events are written at the current version.
+        run(&mut account, [Command::Deposit(1, log())]);
+        let stored = journal.load(&id).expect("load").events;
+        assert_eq!(stored[2].version, 2);
+
+        // A record from a newer schema fails recovery instead of being misread.
+        let future = JournalEvent {
+            seq: 4,
+            version: 3,
+            payload: serde_json::json!({}),
+        };
+        journal.append(&id, &[future]).expect("append future event");
+        let mut newer = Persistent::new(Account::named("old"), journal);
+        let err = newer.recover().expect_err("newer version");
+        assert!(matches!(err, PersistenceError::Upcast { seq: 4, version: 3, .. }), "{err}");
+        assert!(newer.is_halted());
+        crate::test_complete!("upcaster_applies_to_old_events");
+    }
+
+    #[test]
+    fn file_journal_compacts_and_tolerates_damage() {
+        init_test("file_journal_compacts_and_tolerates_damage");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let id = PersistenceId::new("account/f");
+        let log = log();
+
+        let journal = FileEventJournal::new(dir.path()).expect("journal");
+        let path = journal.path(&id);
+        let mut account = Persistent::new(Account::named("f"), journal)
+            .with_snapshot_every(4);
+        account.recover().expect("fresh recovery");
+        run(&mut account, (1..=4).map(|cents| Command::Deposit(cents, log.clone())));
+        let lines = |path: &Path| std::fs::read_to_string(path).unwrap().lines().count();
+        // The snapshot replaced the four event records.
+        crate::assert_with_log!(lines(&path) == 1, "records after snapshot", 1, lines(&path));
+        run(&mut account, (5..=7).map(|cents| Command::Deposit(cents, log.clone())));
+        drop(account);
+
+        // Damage the record of event 6; event 7 after it cannot be trusted.
+        let text = std::fs::read_to_string(&path).unwrap();
+        let damaged: Vec<String> = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                if index == 2 {
+                    line.replace("\"cents\":6", "\"cents\":9")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        std::fs::write(&path, damaged.join("\n") + "\n").unwrap();
+
+        let strict = FileEventJournal::new(dir.path()).expect("journal").strict();
+        let mut account = Persistent::new(Account::named("f"), strict);
+        let err = account.recover().expect_err("strict journal");
+        assert!(matches!(err, PersistenceError::Corrupt { record: 3, .. }), "{err}");
+
+        let tolerant = FileEventJournal::new(dir.path()).expect("journal");
+        let mut account = Persistent::new(Account::named("f"), tolerant);
+        let report = account.recover().expect("tolerant recovery");
+        assert_eq!(report.discarded, 2);
+        assert_eq!(account.actor().snapshot(), (15, 5));
+        // The damaged tail was cut off, so appends continue after seq 5.
+        run(&mut account, [Command::Deposit(100, log.clone())]);
+        assert_eq!(account.last_seq(), 6);
+        assert_eq!(lines(&path), 3);
+        crate::test_complete!("file_journal_compacts_and_tolerates_damage");
+    }
+
+    #[test]
+    fn replies_follow_appends_and_recovery_precedes_queued_messages() {
+        init_test("replies_follow_appends_and_recovery_precedes_queued_messages");
+        let journal = MemoryEventJournal::new();
+        let log = log();
+        let mut seed = Persistent::new(Account::named("lab"), journal.clone());
+        seed.recover().expect("fresh recovery");
+        run(&mut seed, [Command::Deposit(10, log.clone())]);
+        drop(seed);
+        log.lock().clear();
+
+        let mut runtime = LabRuntime::new(LabConfig::new(0x720));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let scope = Scope::<FailFast>::new(region, Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let recording = RecordingJournal {
+            inner: journal,
+            log: log.clone(),
+            fail_appends: false,
+        };
+        // A fresh state: everything it knows comes from recovery.
+        let actor = Persistent::new(Account::named("lab"), recording);
+        let (handle, stored) = scope
+            .spawn_actor(&mut runtime.state, &cx, actor, 8)
+            .expect("spawn");
+        let task_id = handle.task_id();
+        runtime.state.store_spawned_task(task_id, stored);
+
+        // Queued before the actor ever runs, so they wait out recovery.
+        handle.try_send(Command::Deposit(5, log.clone())).unwrap();
+        handle.try_send(Command::Withdraw(100, log.clone())).unwrap();
+        handle.try_send(Command::Withdraw(15, log.clone())).unwrap();
+        drop(handle);
+        runtime.scheduler.lock().schedule(task_id, 0);
+        runtime.run_until_quiescent();
+
+        let observed = log.lock().clone();
+        let expected = [
+            "append 2",
+            "reply 5 -> 15",
+            "rejected 100",
+            "append 3",
+            "reply 15 -> 0",
+        ];
+        crate::assert_with_log!(observed == expected, "event order", expected, observed);
+        crate::test_complete!("replies_follow_appends_and_recovery_precedes_queued_messages");
+    }
+
+    #[test]
+    fn failed_append_drops_reply_and_halts() {
+        init_test("failed_append_drops_reply_and_halts");
+        let log = log();
+        let journal = RecordingJournal {
+            inner: MemoryEventJournal::new(),
+            log: log.clone(),
+            fail_appends: true,
+        };
+        let mut account = Persistent::new(Account::named("h"), journal);
+        account.recover().expect("fresh recovery");
+        let cx = Cx::for_testing();
+
+        let err = account
+            .process(&cx, Command::Deposit(5, log.clone()))
+            .expect_err("append fails");
+        assert!(matches!(err, PersistenceError::Io(_)), "{err}");
+        assert!(account.is_halted());
+        assert_eq!(account.actor().balance, 0);
+        let err = account
+            .process(&cx, Command::Withdraw(1, log.clone()))
+            .expect_err("halted");
+        assert!(matches!(err, PersistenceError::Halted(_)), "{err}");
+        crate::assert_with_log!(log.lock().is_empty(), "no replies", "[]", log.lock().clone());
+        crate::test_complete!("failed_append_drops_reply_and_halts");
+    }
+}
@@ -0,0 +1,469 @@
+//! Journals backing [`Persistent`](super::Persistent) actors.
+//!
+//! A journal holds, per [`PersistenceId`], the latest snapshot and the events
+//! persisted after it. Every call must be durable when it returns: the actor
+//! applies events and replies only after [`EventJournal::append`] succeeded.
+
+use crate::tracing_compat::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Stable identity of a persistent actor's journal.
+///
+/// Two actors sharing a journal only see each other's events if they share
+/// an identifier, so it must not depend on anything that changes across
+/// restarts (task ids, addresses).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct PersistenceId(String);
+
+impl PersistenceId {
+    /// Creates a persistence identifier.
+    #[must_use]
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the identifier.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// File-name form: anything but ASCII alphanumerics, `-` and `_` is
+    /// `%XX`-escaped, so distinct identifiers never share a file.
+    fn file_stem(&self) -> String {
+        let mut stem = String::with_capacity(self.0.len());
+        for byte in self.0.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                stem.push(char::from(byte));
+            } else {
+                let _ = write!(stem, "%{byte:02X}");
+            }
+        }
+        stem
+    }
+}
+
+impl fmt::Display for PersistenceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Error raised while persisting or recovering an actor.
+#[derive(Debug, thiserror::Error)]
+pub enum PersistenceError {
+    /// Reading or writing the journal failed.
+    #[error("event journal I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A journal record could not be read back.
+    #[error("corrupt event journal for {id} at record {record}: {message}")]
+    Corrupt {
+        /// Journal the record belongs to.
+        id: PersistenceId,
+        /// 1-based index of the bad record.
+        record: usize,
+        /// What was wrong with it.
+        message: String,
+    },
+    /// An event or snapshot could not be encoded or decoded.
+    #[error("cannot encode or decode state of {id}: {message}")]
+    Codec {
+        /// Journal being written or replayed.
+        id: PersistenceId,
+        /// Serializer message.
+        message: String,
+    },
+    /// A stored event or snapshot could not be brought to the current schema.
+    #[error("cannot upcast {id} record {seq} from version {version}: {message}")]
+    Upcast {
+        /// Journal being replayed.
+        id: PersistenceId,
+        /// Sequence number of the record.
+        seq: u64,
+        /// Schema version it was written with.
+        version: u32,
+        /// Upcaster message.
+        message: String,
+    },
+    /// An append did not directly follow the journal's last event.
+    #[error("out-of-order append to {id}: expected seq {expected}, got {found}")]
+    OutOfOrder {
+        /// Journal appended to.
+        id: PersistenceId,
+        /// Sequence number the journal expected next.
+        expected: u64,
+        /// Sequence number offered.
+        found: u64,
+    },
+    /// The actor has not finished recovery.
+    #[error("{0} has not recovered yet")]
+    NotRecovered(PersistenceId),
+    /// The actor stopped persisting after an earlier journal failure.
+    #[error("{0} halted after a journal failure")]
+    Halted(PersistenceId),
+}
+
+/// One persisted event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEvent {
+    /// Position in the actor's event stream, starting at 1.
+    pub seq: u64,
+    /// Schema version the event was written with.
+    pub version: u32,
+    /// Encoded event.
+    pub payload: serde_json::Value,
+}
+
+/// A persisted snapshot of an actor's state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalSnapshot {
+    /// Sequence number of the last event the snapshot includes.
+    pub seq: u64,
+    /// Schema version the snapshot was written with.
+    pub version: u32,
+    /// Encoded state.
+    pub payload: serde_json::Value,
+}
+
+/// What a journal holds for one actor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadedJournal {
+    /// Latest snapshot, if any.
+    pub snapshot: Option<JournalSnapshot>,
+    /// Events after the snapshot, in sequence order.
+    pub events: Vec<JournalEvent>,
+    /// Damaged or torn records dropped while loading.
+    pub discarded: usize,
+}
+
+impl LoadedJournal {
+    fn last_seq(&self) -> u64 {
+        self.events
+            .last()
+            .map(|event| event.seq)
+            .or_else(|| self.snapshot.as_ref().map(|snapshot| snapshot.seq))
+            .unwrap_or(0)
+    }
+}
+
+/// Durable storage for persistent actors' events and snapshots.
+pub trait EventJournal: Send {
+    /// Loads the latest snapshot of `id` and the events after it.
+    fn load(&mut self, id: &PersistenceId) -> Result<LoadedJournal, PersistenceError>;
+
+    /// Appends `events` as one atomic batch; the first must directly follow
+    /// the last stored sequence number.
+    fn append(
+        &mut self,
+        id: &PersistenceId,
+        events: &[JournalEvent],
+    ) -> Result<(), PersistenceError>;
+
+    /// Stores `snapshot` in place of the previous one and discards the
+    /// events it covers.
+    fn save_snapshot(
+        &mut self,
+        id: &PersistenceId,
+        snapshot: JournalSnapshot,
+    ) -> Result<(), PersistenceError>;
+}
+
+fn check_follows(
+    id: &PersistenceId,
+    last_seq: u64,
+    events: &[JournalEvent],
+) -> Result<(), PersistenceError> {
+    let mut expected = last_seq.saturating_add(1);
+    for event in events {
+        if event.seq != expected {
+            return Err(PersistenceError::OutOfOrder {
+                id: id.clone(),
+                expected,
+                found: event.seq,
+            });
+        }
+        expected = expected.saturating_add(1);
+    }
+    Ok(())
+}
+
+/// Journal keeping events in memory.
+///
+/// Clones share the journals, so an actor "restarted" with a clone resumes;
+/// a fresh journal behaves like a lost disk.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryEventJournal {
+    logs: Arc<Mutex<BTreeMap<PersistenceId, LoadedJournal>>>,
+}
+
+impl MemoryEventJournal {
+    /// Creates an empty journal.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of events retained for `id` after its latest snapshot.
+    #[must_use]
+    pub fn retained(&self, id: &PersistenceId) -> usize {
+        self.logs.lock().get(id).map_or(0, |log| log.events.len())
+    }
+}
+
+impl EventJournal for MemoryEventJournal {
+    fn load(&mut self, id: &PersistenceId) -> Result<LoadedJournal, PersistenceError> {
+        Ok(self.logs.lock().get(id).cloned().unwrap_or_default())
+    }
+
+    fn append(
+        &mut self,
+        id: &PersistenceId,
+        events: &[JournalEvent],
+    ) -> Result<(), PersistenceError> {
+        let mut logs = self.logs.lock();
+        let log = logs.entry(id.clone()).or_default();
+        check_follows(id, log.last_seq(), events)?;
+        log.events.extend_from_slice(events);
+        drop(logs);
+        Ok(())
+    }
+
+    fn save_snapshot(
+        &mut self,
+        id: &PersistenceId,
+        snapshot: JournalSnapshot,
+    ) -> Result<(), PersistenceError> {
+        let mut logs = self.logs.lock();
+        let log = logs.entry(id.clone()).or_default();
+        log.events.retain(|event| event.seq > snapshot.seq);
+        log.snapshot = Some(snapshot);
+        drop(logs);
+        Ok(())
+    }
+}
+
+/// One line of a journal file, after its checksum.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalRecord {
+    Snapshot { snapshot: JournalSnapshot },
+    Events { events: Vec<JournalEvent> },
+}
+
+#[derive(Debug)]
+struct OpenLog {
+    file: File,
+    last_seq: u64,
+}
+
+/// Journal keeping one append-only file per actor.
+///
+/// Each line is one record, a snapshot or a batch of events, prefixed by
+/// the CRC-32 of its JSON, and is synced before the call returns. Saving a
+/// snapshot rewrites the file (through a temporary file and a rename) with
+/// the snapshot and any later events, so recovery never replays events a
+/// snapshot already covers.
+///
+/// Reads are tolerant by default: a torn or damaged record ends the
+/// journal, the file is truncated to the last good record, and the number
+/// of dropped records is reported in [`LoadedJournal::discarded`]. Nothing
+/// after a damaged record is applied, so the recovered state is always a
+/// state the actor actually passed through. [`strict`](Self::strict)
+/// journals fail instead, except for a torn final record, which is the
+/// normal trace of a crash mid-append.
+#[derive(Debug)]
+pub struct FileEventJournal {
+    dir: PathBuf,
+    strict: bool,
+    open: BTreeMap<PersistenceId, OpenLog>,
+}
+
+impl FileEventJournal {
+    /// Stores journals in `dir`, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, PersistenceError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            strict: false,
+            open: BTreeMap::new(),
+        })
+    }
+
+    /// Fails loads on damaged records instead of truncating them.
+    #[must_use]
+    pub const fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Path of the journal file for `id`.
+    #[must_use]
+    pub fn path(&self, id: &PersistenceId) -> PathBuf {
+        self.dir.join(format!("{}.events", id.file_stem()))
+    }
+
+    /// Reads the journal at `path`, truncating damage in tolerant mode.
+    fn replay(&self, id: &PersistenceId, path: &Path) -> Result<LoadedJournal, PersistenceError> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(LoadedJournal::default());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let mut journal = LoadedJournal::default();
+        let mut valid_len = 0;
+        let mut lines = bytes.split_inclusive(|&byte| byte == b'\n').enumerate();
+        for (index, line) in lines.by_ref() {
+            let Some(line) = line.strip_suffix(b"\n") else {
+                // Torn final record: the append never returned.
+                journal.discarded += 1;
+                break;
+            };
+            match decode_line(line).and_then(|record| apply(&mut journal, record)) {
+                Ok(()) => valid_len += line.len() + 1,
+                Err(message) if self.strict => {
+                    return Err(PersistenceError::Corrupt {
+                        id: id.clone(),
+                        record: index + 1,
+                        message,
+                    });
+                }
+                Err(_message) => {
+                    warn!(
+                        journal = %id,
+                        record = index + 1,
+                        error = %_message,
+                        "discarding damaged event journal tail"
+                    );
+                    journal.discarded += 1;
+                    break;
+                }
+            }
+        }
+        journal.discarded += lines.count();
+        if journal.discarded > 0 {
+            let file = OpenOptions::new().write(true).open(path)?;
+            file.set_len(valid_len as u64)?;
+            file.sync_all()?;
+        }
+        Ok(journal)
+    }
+
+    /// Replays the journal of `id` and opens it for appending.
+    fn open_log(&self, id: &PersistenceId) -> Result<(LoadedJournal, OpenLog), PersistenceError> {
+        let path = self.path(id);
+        let journal = self.replay(id, &path)?;
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let last_seq = journal.last_seq();
+        Ok((journal, OpenLog { file, last_seq }))
+    }
+
+    fn log(&mut self, id: &PersistenceId) -> Result<&mut OpenLog, PersistenceError> {
+        let log = match self.open.remove(id) {
+            Some(log) => log,
+            None => self.open_log(id)?.1,
+        };
+        Ok(self.open.entry(id.clone()).or_insert(log))
+    }
+}
+
+impl EventJournal for FileEventJournal {
+    fn load(&mut self, id: &PersistenceId) -> Result<LoadedJournal, PersistenceError> {
+        self.open.remove(id);
+        let (journal, log) = self.open_log(id)?;
+        self.open.insert(id.clone(), log);
+        Ok(journal)
+    }
+
+    fn append(
+        &mut self,
+        id: &PersistenceId,
+        events: &[JournalEvent],
+    ) -> Result<(), PersistenceError> {
+        let Some(last) = events.last() else {
+            return Ok(());
+        };
+        let log = self.log(id)?;
+        check_follows(id, log.last_seq, events)?;
+        let record = JournalRecord::Events {
+            events: events.to_vec(),
+        };
+        write_record(&mut log.file, &record)?;
+        log.file.sync_data()?;
+        log.last_seq = last.seq;
+        Ok(())
+    }
+
+    fn save_snapshot(
+        &mut self,
+        id: &PersistenceId,
+        snapshot: JournalSnapshot,
+    ) -> Result<(), PersistenceError> {
+        self.open.remove(id);
+        let path = self.path(id);
+        let mut retained = self.replay(id, &path)?.events;
+        retained.retain(|event| event.seq > snapshot.seq);
+        let last_seq = retained.last().map_or(snapshot.seq, |event| event.seq);
+
+        let staging = path.with_extension("events.tmp");
+        {
+            let mut file = File::create(&staging)?;
+            write_record(&mut file, &JournalRecord::Snapshot { snapshot })?;
+            if !retained.is_empty() {
+                write_record(&mut file, &JournalRecord::Events { events: retained })?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&staging, &path)?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        self.open.insert(id.clone(), OpenLog { file, last_seq });
+        Ok(())
+    }
+}
+
+fn decode_line(line: &[u8]) -> Result<JournalRecord, String> {
+    let line = std::str::from_utf8(line).map_err(|err| err.to_string())?;
+    let (crc, body) = line
+        .split_once(' ')
+        .ok_or_else(|| "missing checksum".to_string())?;
+    let crc = u32::from_str_radix(crc, 16).map_err(|err| err.to_string())?;
+    if crc32fast::hash(body.as_bytes()) != crc {
+        return Err("checksum mismatch".to_string());
+    }
+    serde_json::from_str(body).map_err(|err| err.to_string())
+}
+
+fn apply(journal: &mut LoadedJournal, record: JournalRecord) -> Result<(), String> {
+    match record {
+        JournalRecord::Snapshot { snapshot } => {
+            journal.events.retain(|event| event.seq > snapshot.seq);
+            journal.snapshot = Some(snapshot);
+        }
+        JournalRecord::Events { events } => {
+            let mut expected = journal.last_seq().saturating_add(1);
+            for event in &events {
+                if event.seq != expected {
+                    return Err(format!("expected seq {expected}, found {}", event.seq));
+                }
+                expected = expected.saturating_add(1);
+            }
+            journal.events.extend(events);
+        }
+    }
+    Ok(())
+}
+
+fn write_record(file: &mut File, record: &JournalRecord) -> io::Result<()> {
+    let body = serde_json::to_string(record).map_err(io::Error::other)?;
+    let line = format!("{:08x} {body}\n", crc32fast::hash(body.as_bytes()));
+    file.write_all(line.as_bytes())
+}
@@ -1585,6 +1585,8 @@ impl CompiledApp {
             Some(parent_cx.child_entropy(task_id)),
         )
         .with_logical_clock(logical_clock)
+        .with_deadline_clamp_slack(parent_cx.deadline_clamp_slack())
+        .with_capability_audit(parent_cx.capability_audit().cloned())
         .with_registry_handle(registry_override.or_else(|| parent_cx.registry_handle()))
         .with_remote_cap_handle(parent_cx.remote_cap_handle())
         .with_blocking_pool_handle(parent_cx.blocking_pool_handle())
@@ -1873,6 +1875,14 @@ impl AppHandle {
         &self.supervisor
     }
 
+    /// Mutable access to the root supervisor handle, for draining trapped
+    /// child exits through [`SupervisorHandle::next_exit_decision`].
+    #[must_use]
+    #[inline]
+    pub fn supervisor_mut(&mut self) -> &mut SupervisorHandle {
+        &mut self.supervisor
+    }
+
     /// The registry capability handle, if the app was started with one.
     #[must_use]
     pub fn registry(&self) -> Option<&RegistryHandle> {
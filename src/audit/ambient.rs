@@ -1463,6 +1463,7 @@ fn test_function() {
         "lab/",
         "test_logging.rs",
         "test_utils.rs",
+        "test_utils/",
         "test_ndjson.rs",
         "obligation/conformance_runner.rs",
         "audit/",
@@ -1481,7 +1482,7 @@ fn test_function() {
     /// pinned by file, category, pattern, occurrence count, line, and normalized context in
     /// `ambient_authority_inventory_v1.snap`. Review that snapshot diff and
     /// document intentional production additions before updating the baseline.
-    const AMBIENT_VIOLATION_BASELINE_COUNT: usize = 701;
+    const AMBIENT_VIOLATION_BASELINE_COUNT: usize = 704;
 
     fn src_root() -> PathBuf {
         Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
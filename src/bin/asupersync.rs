@@ -35,24 +35,26 @@ use asupersync::atp::{
 use asupersync::cli::doctor::{
     AdvancedCollaborationEntry, AdvancedDiagnosticsFixture, AdvancedDiagnosticsReportBundle,
     AdvancedRemediationDelta, AdvancedTroubleshootingPlaybook, AdvancedTrustTransition,
-    AgentSwarmStatusSnapshot, DoctorEvidenceAnalysisReport, DoctorEvidenceBundle,
-    DoctorScenarioCoveragePackSmokeReport, DoctorScenarioCoveragePacksContract,
-    DoctorStressSoakContract, DoctorStressSoakSmokeReport, EvidenceTimelineContract,
-    EvidenceTimelineWorkflowTranscript, advanced_diagnostics_report_bundle,
-    agent_swarm_status_contract, analyze_doctor_evidence_report,
-    build_doctor_scenario_coverage_pack_smoke_report, build_doctor_stress_soak_smoke_report,
-    doctor_scenario_coverage_packs_contract, doctor_stress_soak_contract,
-    evidence_timeline_contract, ingest_doctor_evidence_bundle, run_agent_swarm_status_smoke,
+    AgentSwarmStatusSnapshot, CapabilityAuditReport, DoctorEvidenceAnalysisReport,
+    DoctorEvidenceBundle, DoctorScenarioCoveragePackSmokeReport,
+    DoctorScenarioCoveragePacksContract, DoctorStressSoakContract, DoctorStressSoakSmokeReport,
+    EvidenceTimelineContract, EvidenceTimelineWorkflowTranscript,
+    advanced_diagnostics_report_bundle, agent_swarm_status_contract, analyze_doctor_evidence_report,
+    audit_workspace_capabilities, build_doctor_scenario_coverage_pack_smoke_report,
+    build_doctor_stress_soak_smoke_report, doctor_scenario_coverage_packs_contract,
+    doctor_stress_soak_contract, evidence_timeline_contract, ingest_doctor_evidence_bundle,
+    parse_capability_usage_log, run_agent_swarm_status_smoke,
     run_evidence_timeline_keyboard_flow_smoke, validate_advanced_diagnostics_report_extension,
     validate_advanced_diagnostics_report_extension_contract,
     validate_doctor_evidence_analysis_report, validate_doctor_evidence_bundle,
 };
 use asupersync::cli::{
-    AtpDoctorArgs, AtpProofArgs, AtpReplayArgs, AtpVerifyArgs, CliError, ColorChoice, CommonArgs,
-    CoreDiagnosticsReport, CoreDiagnosticsReportBundle, CoreDiagnosticsSummary, ExitCode,
-    InvariantAnalyzerReport, LockContentionAnalyzerReport, OperatorModelContract, Output,
-    OutputFormat, Outputtable, RemediationRecipeBundle, ScreenEngineContract,
-    StructuredLoggingContract, WorkspaceScanReport, analyze_workspace_invariants,
+    AtpDoctorArgs, AtpProofArgs, AtpReplayArgs, AtpVerifyArgs, BlockingCallAnalyzerReport,
+    CliError, ColorChoice, CommonArgs, CoreDiagnosticsReport, CoreDiagnosticsReportBundle,
+    CoreDiagnosticsSummary, ExitCode, InvariantAnalyzerReport, LockContentionAnalyzerReport,
+    OperatorModelContract, Output, OutputFormat, Outputtable, RemediationRecipeBundle,
+    ScreenEngineContract, StructuredLoggingContract, WorkspaceScanReport,
+    analyze_workspace_blocking_calls, analyze_workspace_invariants,
     analyze_workspace_lock_contention, core_diagnostics_report_bundle,
     core_diagnostics_report_contract, core_diagnostics_report_fixtures, operator_model_contract,
     parse_color_choice, parse_output_format, remediation_recipe_bundle, scan_workspace,
@@ -64,6 +66,10 @@ use asupersync::conformance::{
     requirements_from_entries, scan_conformance_attributes,
 };
 use asupersync::cx::{Cx, NoCaps};
+use asupersync::evidence::{
+    EvidenceIndex, MigrationRegistry, PredicateOp, Query as EvidenceQuery, QuerySummary,
+    SchemaVersion, validate_corpus,
+};
 use asupersync::lab::dual_run::{
     FinalDivergenceClass, ReplayPolicy, RerunDecision, SeedPlan, derive_scenario_seed,
 };
@@ -81,6 +87,7 @@ use asupersync::observability::{
     TASK_CONSOLE_WIRE_SCHEMA_V1, TaskConsoleWireSnapshot, TaskDetailsWire, TaskSummaryWire,
 };
 use asupersync::sync::{AcquireError, Semaphore};
+use asupersync::trace::analysis::{DiffOptions, TraceDiffReport, semantic_diff};
 use asupersync::trace::{
     CompressionMode, IssueSeverity, ReplayEvent, TRACE_FILE_VERSION, TRACE_MAGIC, TraceFileConfig,
     TraceFileError, TraceReader, TraceWriter, VerificationOptions, verify_trace,
@@ -159,12 +166,16 @@ enum Command {
     Atp(AtpArgs),
     /// Trace file inspection utilities
     Trace(TraceArgs),
+    /// Evidence ledger query tooling
+    Evidence(EvidenceArgs),
     /// Conformance tooling
     Conformance(ConformanceArgs),
     /// FrankenLab scenario testing (bd-1hu19.4)
     Lab(LabArgs),
     /// Doctor tooling for deterministic workspace diagnostics
     Doctor(DoctorArgs),
+    /// Dump the live supervision tree from a running debug server
+    DumpSuptree(DumpSuptreeArgs),
 }
 
 #[derive(Args, Debug)]
@@ -2770,7 +2781,7 @@ enum TraceCommand {
     /// Verify trace file integrity
     Verify(TraceVerifyArgs),
 
-    /// Diff two trace files
+    /// Semantically diff two trace files, aligning events by structure
     Diff(TraceDiffArgs),
 
     /// Rewrite a trace file with LZ4 compression
@@ -2815,6 +2826,35 @@ struct ConformanceMatrixArgs {
     fail_on_missing: bool,
 }
 
+// =========================================================================
+// Supervision tree CLI
+// =========================================================================
+
+#[derive(Args, Debug)]
+struct DumpSuptreeArgs {
+    /// Address of the runtime's debug server
+    #[arg(long, value_name = "HOST:PORT", default_value = "127.0.0.1:9999")]
+    addr: String,
+
+    /// Tree format: json or dot
+    #[arg(long = "tree-format", value_enum, default_value_t = SuptreeFormat::Json)]
+    tree_format: SuptreeFormat,
+
+    /// Write the tree to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Connect and read timeout in milliseconds
+    #[arg(long = "timeout-ms", default_value_t = 5_000)]
+    timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SuptreeFormat {
+    Json,
+    Dot,
+}
+
 // =========================================================================
 // FrankenLab CLI (bd-1hu19.4)
 // =========================================================================
@@ -2990,8 +3030,12 @@ enum DoctorCommand {
     AnalyzeInvariants(DoctorAnalyzeInvariantsArgs),
     /// Analyze lock-order and contention risk over scanner output
     AnalyzeLockContention(DoctorAnalyzeLockContentionArgs),
+    /// Detect known-blocking calls reachable from async fns
+    AnalyzeBlockingCalls(DoctorAnalyzeBlockingCallsArgs),
     /// Audit wasm-target dependency graph for forbidden runtime crates
     WasmDependencyAudit(DoctorWasmDependencyAuditArgs),
+    /// Diff capability manifests against static and runtime capability usage
+    CapabilityAudit(DoctorCapabilityAuditArgs),
     /// Emit operator personas, missions, and decision loops contract
     OperatorModel,
     /// Emit canonical screen-to-engine contract for doctor TUI surfaces
@@ -3091,6 +3135,13 @@ struct DoctorAnalyzeLockContentionArgs {
     root: PathBuf,
 }
 
+#[derive(Args, Debug)]
+struct DoctorAnalyzeBlockingCallsArgs {
+    /// Workspace root to scan and analyze
+    #[arg(long = "root", default_value = ".")]
+    root: PathBuf,
+}
+
 #[derive(Args, Debug)]
 struct DoctorWasmDependencyAuditArgs {
     /// Workspace root where Cargo.toml lives
@@ -3110,6 +3161,21 @@ struct DoctorWasmDependencyAuditArgs {
     report: Option<PathBuf>,
 }
 
+#[derive(Args, Debug)]
+struct DoctorCapabilityAuditArgs {
+    /// Workspace root whose members carry `capabilities.toml` manifests
+    #[arg(long = "root", default_value = ".")]
+    root: PathBuf,
+
+    /// Runtime capability usage log to include (repeatable)
+    #[arg(long = "usage-log", value_name = "PATH")]
+    usage_logs: Vec<PathBuf>,
+
+    /// Optional report path to write JSON output
+    #[arg(long = "report")]
+    report: Option<PathBuf>,
+}
+
 #[derive(Args, Debug)]
 struct DoctorFrankenExportArgs {
     /// Optional path to a core diagnostics report JSON payload
@@ -3304,6 +3370,18 @@ struct TraceDiffArgs {
 
     /// Second trace file
     file_b: PathBuf,
+
+    /// Number of divergences to show in full
+    #[arg(long = "limit", default_value_t = 10)]
+    limit: usize,
+
+    /// Events of context shown before each divergence
+    #[arg(long = "context", default_value_t = 3)]
+    context: usize,
+
+    /// Lookahead per trace when matching reordered events
+    #[arg(long = "window", default_value_t = 64)]
+    window: usize,
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy)]
@@ -3335,6 +3413,77 @@ struct TraceCompressArgs {
     level: i32,
 }
 
+#[derive(Args, Debug)]
+struct EvidenceArgs {
+    #[command(subcommand)]
+    command: EvidenceCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum EvidenceCommand {
+    /// Query persisted NDJSON evidence ledgers
+    Query(EvidenceQueryArgs),
+
+    /// Rebuild the sidecar query index of evidence files
+    Reindex(EvidenceReindexArgs),
+
+    /// Check that evidence files can be read at a target schema version
+    Validate(EvidenceValidateArgs),
+}
+
+#[derive(Args, Debug)]
+struct EvidenceQueryArgs {
+    /// Evidence JSONL files to scan
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Only match entries from this component
+    #[arg(long = "component")]
+    component: Option<String>,
+
+    /// Only match entries with this chosen action
+    #[arg(long = "action")]
+    action: Option<String>,
+
+    /// Field predicate: FIELD=VALUE, FIELD!=VALUE, FIELD>=NUMBER or FIELD<=NUMBER
+    #[arg(long = "where", value_name = "PREDICATE")]
+    predicates: Vec<String>,
+
+    /// Earliest timestamp to match, in Unix milliseconds (inclusive)
+    #[arg(long = "since")]
+    since: Option<u64>,
+
+    /// Latest timestamp to match, in Unix milliseconds (inclusive)
+    #[arg(long = "until")]
+    until: Option<u64>,
+
+    /// Stop after N matching entries
+    #[arg(long = "limit")]
+    limit: Option<usize>,
+
+    /// Scan every file even when a sidecar index proves it cannot match
+    #[arg(long = "no-index", action = ArgAction::SetTrue)]
+    no_index: bool,
+}
+
+#[derive(Args, Debug)]
+struct EvidenceReindexArgs {
+    /// Evidence JSONL files to index
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct EvidenceValidateArgs {
+    /// Evidence JSONL files to check
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Schema version every entry must be readable at (MAJOR.MINOR)
+    #[arg(long = "target", default_value_t = SchemaVersion::CURRENT)]
+    target: SchemaVersion,
+}
+
 #[derive(Debug, serde::Serialize)]
 struct TraceInfo {
     file: String,
@@ -3380,6 +3529,21 @@ impl Outputtable for TraceInfo {
     }
 }
 
+#[derive(Debug, serde::Serialize)]
+struct EvidenceQueryOutput {
+    entries: Vec<EvidenceLedger>,
+    summary: QuerySummary,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct EvidenceIndexRow {
+    file: String,
+    sidecar: String,
+    entries: u64,
+    min_ts_unix_ms: Option<u64>,
+    max_ts_unix_ms: Option<u64>,
+}
+
 #[derive(Debug, serde::Serialize)]
 struct TraceEventRow {
     index: u64,
@@ -3480,40 +3644,20 @@ struct TraceDiffOutput {
     file_b: String,
     diverged: bool,
     divergence_index: Option<u64>,
-    event_a: Option<ReplayEvent>,
-    event_b: Option<ReplayEvent>,
     common_events: u64,
     total_a: u64,
     total_b: u64,
+    report: TraceDiffReport,
 }
 
 impl Outputtable for TraceDiffOutput {
     fn human_format(&self) -> String {
-        let mut lines = Vec::new();
-        if self.diverged {
-            if let Some(index) = self.divergence_index {
-                lines.push(format!("First divergence at event #{index}"));
-            } else {
-                lines.push("Traces diverged".to_string());
-            }
-            if let Some(event_a) = &self.event_a {
-                lines.push(format!("  File A: {event_a:?}"));
-            } else {
-                lines.push("  File A: <end>".to_string());
-            }
-            if let Some(event_b) = &self.event_b {
-                lines.push(format!("  File B: {event_b:?}"));
-            } else {
-                lines.push("  File B: <end>".to_string());
-            }
-        } else {
-            lines.push("Traces are identical".to_string());
-        }
-        lines.push(format!(
-            "Common events: {} (A={}, B={})",
-            self.common_events, self.total_a, self.total_b
-        ));
-        lines.join("\n")
+        format!(
+            "File A: {}\nFile B: {}\n{}",
+            self.file_a,
+            self.file_b,
+            self.report.to_string().trim_end()
+        )
     }
 }
 
@@ -3599,9 +3743,11 @@ fn run(command: Command, output: &mut Output) -> Result<(), CliError> {
     match command {
         Command::Atp(args) => run_atp(args, output),
         Command::Trace(trace_args) => run_trace(trace_args, output),
+        Command::Evidence(args) => run_evidence(args, output),
         Command::Conformance(args) => run_conformance(args, output),
         Command::Lab(args) => run_lab(args, output),
         Command::Doctor(args) => run_doctor(args, output),
+        Command::DumpSuptree(args) => run_dump_suptree(&args, output),
     }
 }
 
@@ -5076,7 +5222,7 @@ fn atp_trace(args: &AtpTraceArgs, output: &mut Output) -> Result<(), CliError> {
                 }
             }
 
-            let diff = trace_diff(trace_a, trace_b)?;
+            let diff = trace_diff(trace_a, trace_b, &DiffOptions::default())?;
             let info_a = trace_info(trace_a)?;
             let info_b = trace_info(trace_b)?;
             let requested_metrics = if metrics.is_empty() {
@@ -5454,12 +5600,16 @@ fn run_trace(args: TraceArgs, output: &mut Output) -> Result<(), CliError> {
             Ok(())
         }
         TraceCommand::Diff(args) => {
-            let out = trace_diff(&args.file_a, &args.file_b)?;
-            let diverged = out.diverged;
+            let options = DiffOptions::default()
+                .with_window(args.window)
+                .with_max_divergences(args.limit)
+                .with_context(args.context);
+            let out = trace_diff(&args.file_a, &args.file_b, &options)?;
+            let significant = out.report.has_significant();
             output
                 .write(&out)
                 .map_err(output_write_error("diff results"))?;
-            if diverged {
+            if significant {
                 return Err(CliError::new("trace_divergence", "Traces diverged")
                     .exit_code(ExitCode::TRACE_MISMATCH));
             }
@@ -5479,12 +5629,227 @@ fn run_trace(args: TraceArgs, output: &mut Output) -> Result<(), CliError> {
     }
 }
 
+fn run_evidence(args: EvidenceArgs, output: &mut Output) -> Result<(), CliError> {
+    match args.command {
+        EvidenceCommand::Query(args) => {
+            let result = evidence_query(&args)?;
+            output
+                .write(&JsonOutputValue::new(result))
+                .map_err(output_write_error("evidence query results"))?;
+            Ok(())
+        }
+        EvidenceCommand::Reindex(args) => {
+            let rows = evidence_reindex(&args.files)?;
+            output
+                .write(&JsonOutputValue::new(rows))
+                .map_err(output_write_error("evidence index summary"))?;
+            Ok(())
+        }
+        EvidenceCommand::Validate(args) => {
+            let report = validate_corpus(&MigrationRegistry::new(), args.target, &args.files)
+                .map_err(|err| {
+                    CliError::new("io_error", "Failed to read evidence").detail(err.to_string())
+                })?;
+            let clean = report.is_clean();
+            let failures = report.failures.len();
+            output
+                .write(&JsonOutputValue::new(report))
+                .map_err(output_write_error("evidence validation report"))?;
+            if !clean {
+                return Err(CliError::new(
+                    "evidence_incompatible",
+                    "Evidence entries cannot be read at the target schema version",
+                )
+                .detail(format!("{failures} entries failed validation"))
+                .exit_code(ExitCode::TEST_FAILURE));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn evidence_query(args: &EvidenceQueryArgs) -> Result<EvidenceQueryOutput, CliError> {
+    let mut query = EvidenceQuery::new().use_index(!args.no_index);
+    if let Some(component) = &args.component {
+        query = query.component(component);
+    }
+    if let Some(action) = &args.action {
+        query = query.action(action);
+    }
+    for raw in &args.predicates {
+        let (field, op, value) = parse_evidence_predicate(raw)?;
+        query = query.predicate(field, op, value);
+    }
+    if args.since.is_some() || args.until.is_some() {
+        let since = args.since.unwrap_or(0);
+        let until = args.until.unwrap_or(u64::MAX);
+        if since > until {
+            return Err(
+                CliError::new("invalid_argument", "Invalid evidence time range")
+                    .detail(format!("--since {since} is after --until {until}"))
+                    .exit_code(ExitCode::USER_ERROR),
+            );
+        }
+        query = query.time_range(since, until);
+    }
+    if let Some(limit) = args.limit {
+        query = query.limit(limit);
+    }
+
+    let mut scan = query.scan(&args.files);
+    let mut entries = Vec::new();
+    for entry in &mut scan {
+        entries.push(entry.map_err(|err| {
+            CliError::new("io_error", "Failed to read evidence").detail(err.to_string())
+        })?);
+    }
+    Ok(EvidenceQueryOutput {
+        entries,
+        summary: scan.into_summary(),
+    })
+}
+
+/// Parses `FIELD=VALUE`, `FIELD!=VALUE`, `FIELD>=NUMBER` or `FIELD<=NUMBER`.
+///
+/// Values that parse as JSON (`true`, `3`, `"x"`) keep their type; anything
+/// else is compared as a string.
+fn parse_evidence_predicate(
+    raw: &str,
+) -> Result<(String, PredicateOp, serde_json::Value), CliError> {
+    let parsed = [
+        ("!=", PredicateOp::Ne),
+        (">=", PredicateOp::Ge),
+        ("<=", PredicateOp::Le),
+        ("=", PredicateOp::Eq),
+    ]
+    .into_iter()
+    .find_map(|(token, op)| {
+        raw.split_once(token)
+            .map(|(field, value)| (field.trim(), op, value.trim()))
+    });
+    let Some((field, op, value)) = parsed.filter(|(field, _, _)| !field.is_empty()) else {
+        return Err(
+            CliError::new("invalid_argument", "Invalid evidence predicate")
+                .detail(format!(
+                    "expected FIELD=VALUE, FIELD!=VALUE, FIELD>=NUMBER or FIELD<=NUMBER, got {raw:?}"
+                ))
+                .exit_code(ExitCode::USER_ERROR),
+        );
+    };
+    let value = serde_json::from_str(value)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    Ok((field.to_string(), op, value))
+}
+
+fn evidence_reindex(files: &[PathBuf]) -> Result<Vec<EvidenceIndexRow>, CliError> {
+    files
+        .iter()
+        .map(|path| {
+            let index = EvidenceIndex::reindex(path).map_err(|err| io_error(path, &err))?;
+            Ok(EvidenceIndexRow {
+                file: path.display().to_string(),
+                sidecar: EvidenceIndex::sidecar_path(path).display().to_string(),
+                entries: index.entries,
+                min_ts_unix_ms: index.min_ts_unix_ms,
+                max_ts_unix_ms: index.max_ts_unix_ms,
+            })
+        })
+        .collect()
+}
+
 fn run_conformance(args: ConformanceArgs, output: &mut Output) -> Result<(), CliError> {
     match args.command {
         ConformanceCommand::Matrix(args) => conformance_matrix(args, output),
     }
 }
 
+// =========================================================================
+// Supervision tree handlers
+// =========================================================================
+
+#[derive(Debug, serde::Serialize)]
+struct SuptreeDotOutput {
+    dot: String,
+}
+
+impl Outputtable for SuptreeDotOutput {
+    fn human_format(&self) -> String {
+        self.dot.trim_end().to_string()
+    }
+}
+
+fn run_dump_suptree(args: &DumpSuptreeArgs, output: &mut Output) -> Result<(), CliError> {
+    let target = match args.tree_format {
+        SuptreeFormat::Json => "/debug/suptree",
+        SuptreeFormat::Dot => "/debug/suptree?format=dot",
+    };
+    let body = fetch_debug_endpoint(&args.addr, target, args.timeout_ms)?;
+
+    if let Some(path) = &args.out {
+        return write_text_artifact(path, &body);
+    }
+    match args.tree_format {
+        SuptreeFormat::Json => {
+            let tree: serde_json::Value = serde_json::from_str(&body).map_err(|err| {
+                CliError::new("suptree_parse_error", "Debug server returned invalid JSON")
+                    .detail(err.to_string())
+                    .context("addr", args.addr.clone())
+                    .exit_code(ExitCode::RUNTIME_ERROR)
+            })?;
+            output
+                .write(&JsonOutputValue::new(tree))
+                .map_err(output_write_error("supervision tree"))
+        }
+        SuptreeFormat::Dot => output
+            .write(&SuptreeDotOutput { dot: body })
+            .map_err(output_write_error("supervision tree")),
+    }
+}
+
+/// Issues a `GET` against the runtime debug server and returns the body of a
+/// `200 OK` response.
+fn fetch_debug_endpoint(addr: &str, target: &str, timeout_ms: u64) -> Result<String, CliError> {
+    use std::net::{TcpStream, ToSocketAddrs};
+    use std::time::Duration;
+
+    let connect_error = |detail: String| {
+        CliError::new("debug_server_unreachable", "Failed to reach the debug server")
+            .detail(detail)
+            .context("addr", addr.to_string())
+            .exit_code(ExitCode::RUNTIME_ERROR)
+    };
+    let timeout = Duration::from_millis(timeout_ms.max(1));
+    let socket_addr = addr
+        .to_socket_addrs()
+        .map_err(|err| connect_error(err.to_string()))?
+        .next()
+        .ok_or_else(|| connect_error("address resolved to nothing".to_string()))?;
+    let mut stream = TcpStream::connect_timeout(&socket_addr, timeout)
+        .map_err(|err| connect_error(err.to_string()))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|()| stream.set_write_timeout(Some(timeout)))
+        .map_err(|err| connect_error(err.to_string()))?;
+    write!(stream, "GET {target} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n")
+        .and_then(|()| stream.flush())
+        .map_err(|err| connect_error(err.to_string()))?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .map_err(|err| connect_error(err.to_string()))?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response.as_str(), ""));
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(CliError::new("debug_server_error", "Debug server rejected the request")
+            .detail(status_line.to_string())
+            .context("addr", addr.to_string())
+            .context("target", target.to_string())
+            .exit_code(ExitCode::RUNTIME_ERROR));
+    }
+    Ok(body.to_string())
+}
+
 // =========================================================================
 // Lab (FrankenLab) handlers (bd-1hu19.4)
 // =========================================================================
@@ -5518,9 +5883,13 @@ fn run_doctor(args: DoctorArgs, output: &mut Output) -> Result<(), CliError> {
         DoctorCommand::AnalyzeLockContention(analyze_args) => {
             doctor_analyze_lock_contention(&analyze_args, output)
         }
+        DoctorCommand::AnalyzeBlockingCalls(analyze_args) => {
+            doctor_analyze_blocking_calls(&analyze_args, output)
+        }
         DoctorCommand::WasmDependencyAudit(audit_args) => {
             doctor_wasm_dependency_audit(&audit_args, output)
         }
+        DoctorCommand::CapabilityAudit(audit_args) => doctor_capability_audit(&audit_args, output),
         DoctorCommand::OperatorModel => doctor_operator_model(output),
         DoctorCommand::ScreenContracts => doctor_screen_contracts(output),
         DoctorCommand::LoggingContract => doctor_logging_contract(output),
@@ -5748,6 +6117,26 @@ fn doctor_analyze_lock_contention(
     Ok(())
 }
 
+fn doctor_analyze_blocking_calls(
+    args: &DoctorAnalyzeBlockingCallsArgs,
+    output: &mut Output,
+) -> Result<(), CliError> {
+    let report: WorkspaceScanReport = scan_workspace(&args.root).map_err(|err| {
+        CliError::new(
+            "doctor_scan_error",
+            "Failed to scan workspace for blocking-call analysis",
+        )
+        .detail(err.to_string())
+        .context("root", args.root.display().to_string())
+        .exit_code(ExitCode::RUNTIME_ERROR)
+    })?;
+    let analysis: BlockingCallAnalyzerReport = analyze_workspace_blocking_calls(&report);
+    output.write(&analysis).map_err(|err| {
+        CliError::new("output_error", "Failed to write output").detail(err.to_string())
+    })?;
+    Ok(())
+}
+
 fn doctor_operator_model(output: &mut Output) -> Result<(), CliError> {
     let contract: OperatorModelContract = operator_model_contract();
     output.write(&contract).map_err(|err| {
@@ -8682,6 +9071,72 @@ fn doctor_wasm_dependency_audit(
     Ok(())
 }
 
+fn doctor_capability_audit(
+    args: &DoctorCapabilityAuditArgs,
+    output: &mut Output,
+) -> Result<(), CliError> {
+    let scan: WorkspaceScanReport = scan_workspace(&args.root).map_err(|err| {
+        CliError::new(
+            "doctor_scan_error",
+            "Failed to scan workspace for capability audit",
+        )
+        .detail(err.to_string())
+        .context("root", args.root.display().to_string())
+        .exit_code(ExitCode::RUNTIME_ERROR)
+    })?;
+
+    let mut usage_logs = Vec::with_capacity(args.usage_logs.len());
+    for path in &args.usage_logs {
+        let text = fs::read_to_string(path).map_err(|err| io_error(path, &err))?;
+        let log = parse_capability_usage_log(&text).map_err(|err| {
+            CliError::new(
+                "doctor_capability_usage_log_parse_error",
+                "Failed to parse capability usage log",
+            )
+            .detail(err)
+            .context("path", path.display().to_string())
+            .exit_code(ExitCode::USER_ERROR)
+        })?;
+        usage_logs.push(log);
+    }
+
+    let report: CapabilityAuditReport = audit_workspace_capabilities(&scan, &usage_logs);
+
+    if let Some(path) = &args.report {
+        let serialized = serde_json::to_string_pretty(&report).map_err(|err| {
+            CliError::new(
+                "serialization_error",
+                "Failed to serialize capability audit report",
+            )
+            .detail(err.to_string())
+        })?;
+        fs::write(path, serialized).map_err(|err| io_error(path, &err))?;
+    }
+
+    output.write(&report).map_err(|err| {
+        CliError::new("output_error", "Failed to write output").detail(err.to_string())
+    })?;
+
+    if report.status == "fail" {
+        return Err(CliError::new(
+            "undeclared_capability_usage",
+            "Capability usage does not match the declared manifests",
+        )
+        .detail(
+            report
+                .findings
+                .iter()
+                .filter(|finding| finding.severity == "failure")
+                .map(|finding| finding.finding_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+        .exit_code(ExitCode::TEST_FAILURE));
+    }
+
+    Ok(())
+}
+
 fn normalized_forbidden_crates(extra_forbidden: &[String]) -> Vec<String> {
     const DEFAULT_FORBIDDEN: [&str; 7] = [
         "tokio",
@@ -12055,81 +12510,43 @@ fn trace_verify(
     })
 }
 
-fn trace_diff(path_a: &Path, path_b: &Path) -> Result<TraceDiffOutput, CliError> {
-    let mut reader_a = TraceReader::open(path_a).map_err(|err| trace_file_error(path_a, err))?;
-    let mut reader_b = TraceReader::open(path_b).map_err(|err| trace_file_error(path_b, err))?;
-
-    let total_a = reader_a.event_count();
-    let total_b = reader_b.event_count();
-
-    let mut index = 0u64;
-    loop {
-        let event_a = reader_a
-            .read_event()
-            .map_err(|err| trace_file_error(path_a, err))?;
-        let event_b = reader_b
-            .read_event()
-            .map_err(|err| trace_file_error(path_b, err))?;
-
-        match (event_a, event_b) {
-            (None, None) => {
-                return Ok(TraceDiffOutput {
-                    file_a: path_a.display().to_string(),
-                    file_b: path_b.display().to_string(),
-                    diverged: false,
-                    divergence_index: None,
-                    event_a: None,
-                    event_b: None,
-                    common_events: index,
-                    total_a,
-                    total_b,
-                });
-            }
-            (Some(event_a), Some(event_b)) => {
-                if event_a != event_b {
-                    return Ok(TraceDiffOutput {
-                        file_a: path_a.display().to_string(),
-                        file_b: path_b.display().to_string(),
-                        diverged: true,
-                        divergence_index: Some(index),
-                        event_a: Some(event_a),
-                        event_b: Some(event_b),
-                        common_events: index,
-                        total_a,
-                        total_b,
-                    });
-                }
-            }
-            (Some(event_a), None) => {
-                return Ok(TraceDiffOutput {
-                    file_a: path_a.display().to_string(),
-                    file_b: path_b.display().to_string(),
-                    diverged: true,
-                    divergence_index: Some(index),
-                    event_a: Some(event_a),
-                    event_b: None,
-                    common_events: index,
-                    total_a,
-                    total_b,
-                });
-            }
-            (None, Some(event_b)) => {
-                return Ok(TraceDiffOutput {
-                    file_a: path_a.display().to_string(),
-                    file_b: path_b.display().to_string(),
-                    diverged: true,
-                    divergence_index: Some(index),
-                    event_a: None,
-                    event_b: Some(event_b),
-                    common_events: index,
-                    total_a,
-                    total_b,
-                });
-            }
-        }
-
-        index = index.saturating_add(1);
-    }
+fn trace_diff(
+    path_a: &Path,
+    path_b: &Path,
+    options: &DiffOptions,
+) -> Result<TraceDiffOutput, CliError> {
+    let reader_a = TraceReader::open(path_a).map_err(|err| trace_file_error(path_a, err))?;
+    let reader_b = TraceReader::open(path_b).map_err(|err| trace_file_error(path_b, err))?;
+
+    // The diff streams both files; a read error ends the stream and is
+    // reported once the diff returns.
+    let mut error_a = None;
+    let mut error_b = None;
+    let events_a = reader_a
+        .events()
+        .map_while(|event| event.map_err(|err| error_a = Some(err)).ok());
+    let events_b = reader_b
+        .events()
+        .map_while(|event| event.map_err(|err| error_b = Some(err)).ok());
+    let report = semantic_diff(events_a, events_b, options);
+    if let Some(err) = error_a {
+        return Err(trace_file_error(path_a, err));
+    }
+    if let Some(err) = error_b {
+        return Err(trace_file_error(path_b, err));
+    }
+
+    let diverged = !report.is_identical();
+    Ok(TraceDiffOutput {
+        file_a: path_a.display().to_string(),
+        file_b: path_b.display().to_string(),
+        diverged,
+        divergence_index: diverged.then_some(report.shared_prefix),
+        common_events: report.shared_prefix,
+        total_a: report.events_a,
+        total_b: report.events_b,
+        report,
+    })
 }
 
 fn export_trace(path: &Path, format: ExportFormat) -> Result<(), CliError> {
@@ -12357,6 +12774,8 @@ fn compression_label(mode: CompressionMode) -> String {
         CompressionMode::Lz4 { level } => format!("lz4(level={level})"),
         #[cfg(feature = "trace-compression")]
         CompressionMode::Auto => "auto(lz4)".to_string(),
+        #[cfg(feature = "trace-compression")]
+        CompressionMode::Zstd { level } => format!("zstd(level={level})"),
     }
 }
 
@@ -12438,6 +12857,34 @@ fn trace_file_error(path: &Path, err: TraceFileError) -> CliError {
             CliError::new("oversized_field", "Trace field exceeds allowed limit")
                 .detail(format!("{field}: {actual} bytes (max {max})"))
         }
+        TraceFileError::DictionaryRequired { id, checksum } => {
+            CliError::new("dictionary_required", "Trace requires a zstd dictionary")
+                .detail(format!("Dictionary id {id}, checksum {checksum:#010x}"))
+        }
+        TraceFileError::DictionaryMismatch {
+            expected_id,
+            expected_checksum,
+            found_id,
+            found_checksum,
+        } => CliError::new("dictionary_mismatch", "Trace dictionary mismatch").detail(format!(
+            "Expected id {expected_id} (checksum {expected_checksum:#010x}), \
+             found id {found_id} (checksum {found_checksum:#010x})"
+        )),
+        TraceFileError::FrameChecksumMismatch {
+            frame,
+            expected,
+            actual,
+        } => CliError::new("frame_checksum_mismatch", "Trace frame checksum mismatch").detail(
+            format!("Frame {frame}: expected {expected:#010x}, found {actual:#010x}"),
+        ),
+        TraceFileError::FrameIndexMissing => {
+            CliError::new("frame_index_missing", "Trace file has no frame index")
+        }
+        TraceFileError::FrameOutOfRange { frame, frames } => {
+            CliError::new("frame_out_of_range", "Trace frame out of range").detail(format!(
+                "Frame {frame} requested, trace has {frames} frames"
+            ))
+        }
     }
     .context("path", path.display().to_string())
 }
@@ -12634,6 +13081,73 @@ mod tests {
         assert_eq!(rows[0].kind, "RngSeed");
     }
 
+    #[test]
+    fn evidence_query_filters_and_reports_malformed_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("evidence.jsonl");
+        let entry = |ts: u64, component: &str, fallback: bool| {
+            EvidenceLedgerBuilder::new()
+                .ts_unix_ms(ts)
+                .component(component)
+                .action("select")
+                .posterior(vec![1.0])
+                .chosen_expected_loss(0.0)
+                .calibration_score(1.0)
+                .fallback_active(fallback)
+                .build()
+                .expect("evidence entry")
+        };
+        let mut body = String::new();
+        for ledger in [
+            entry(10, "transport.path_select", true),
+            entry(20, "transport.path_select", false),
+            entry(30, "scheduler", true),
+        ] {
+            body.push_str(&serde_json::to_string(&ledger).expect("serialize entry"));
+            body.push('\n');
+        }
+        body.push_str("{\"ts\":\n");
+        fs::write(&path, body).expect("write evidence");
+
+        let args = EvidenceQueryArgs {
+            files: vec![path.clone()],
+            component: Some("transport.path_select".to_string()),
+            action: None,
+            predicates: vec!["fallback_active=true".to_string()],
+            since: Some(10),
+            until: Some(10),
+            limit: None,
+            no_index: false,
+        };
+        let result = evidence_query(&args).expect("evidence query");
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].ts_unix_ms, 10);
+        assert_eq!(result.summary.entries_skipped, 2);
+        assert_eq!(result.summary.malformed.len(), 1);
+        assert_eq!(result.summary.malformed[0].line, 4);
+
+        let rows = evidence_reindex(&[path.clone()]).expect("reindex");
+        assert_eq!(rows[0].entries, 3);
+        assert!(EvidenceIndex::sidecar_path(&path).exists());
+    }
+
+    #[test]
+    fn evidence_predicate_parsing() {
+        let (field, op, value) = parse_evidence_predicate("fb=true").expect("eq");
+        assert_eq!((field.as_str(), op), ("fb", PredicateOp::Eq));
+        assert_eq!(value, serde_json::Value::Bool(true));
+
+        let (_, op, value) = parse_evidence_predicate("action!=relay").expect("ne");
+        assert_eq!(op, PredicateOp::Ne);
+        assert_eq!(value, serde_json::Value::String("relay".to_string()));
+
+        let (_, op, _) = parse_evidence_predicate("cal>=0.5").expect("ge");
+        assert_eq!(op, PredicateOp::Ge);
+
+        let err = parse_evidence_predicate("=3").expect_err("missing field");
+        assert_eq!(err.error_type, "invalid_argument");
+    }
+
     #[test]
     fn trace_events_filtering_accepts_kebab_case_kind_names() {
         let file = make_sample_trace();
@@ -12678,8 +13192,12 @@ mod tests {
             .expect("write event");
         writer.finish().expect("finish");
 
-        let diff = trace_diff(file_a.path(), file_b.path()).expect("trace diff");
+        let diff = trace_diff(file_a.path(), file_b.path(), &DiffOptions::default())
+            .expect("trace diff");
         assert!(diff.diverged);
+        assert!(diff.report.has_significant());
+        assert_eq!(diff.divergence_index, Some(0));
+        assert_eq!(diff.total_b, 1);
     }
 
     #[test]
@@ -14734,6 +15252,43 @@ lab:
         assert_eq!(format, OutputFormat::JsonPretty);
     }
 
+    #[test]
+    fn dump_suptree_fetches_dot_from_debug_server() {
+        let cli = Cli::try_parse_from(["asupersync", "dump-suptree", "--tree-format", "dot"])
+            .expect("parse dump-suptree command");
+        let Command::DumpSuptree(mut args) = cli.command else {
+            panic!("expected dump-suptree command");
+        };
+        assert_eq!(args.addr, "127.0.0.1:9999");
+        assert_eq!(args.tree_format, SuptreeFormat::Dot);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        args.addr = listener.local_addr().expect("local addr").to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request_line = String::new();
+            io::BufRead::read_line(&mut io::BufReader::new(&stream), &mut request_line)
+                .expect("read request line");
+            let body = "digraph supervision {\n}\n";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .expect("write response");
+            request_line
+        });
+
+        let capture = SharedWrite::default();
+        let mut output = Output::with_writer(OutputFormat::Human, capture.clone());
+        run_dump_suptree(&args, &mut output).expect("dump-suptree command");
+        output.flush().expect("flush suptree output");
+
+        let request_line = server.join().expect("server thread");
+        assert!(request_line.starts_with("GET /debug/suptree?format=dot "));
+        assert_eq!(capture.contents().trim(), "digraph supervision {\n}");
+    }
+
     #[test]
     fn doctor_report_command_emits_checked_json_schema() {
         let capture = SharedWrite::default();
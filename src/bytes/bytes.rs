@@ -4,9 +4,14 @@ use super::buf::Buf;
 use std::ops::{Deref, RangeBounds};
 use std::sync::Arc;
 
+/// Largest payload stored inline: the bytes the heap variants of
+/// `BytesInner` already occupy, minus the discriminant (23 on 64-bit targets).
+const INLINE_CAPACITY: usize = 3 * std::mem::size_of::<usize>() - 1;
+
 /// Immutable byte slice with cheap cloning.
 ///
-/// Cloning a `Bytes` is O(1) - it just increments a reference count.
+/// Cloning a `Bytes` is O(1) - it just increments a reference count (or, for
+/// inline payloads, copies at most [`Bytes::INLINE_CAPACITY`] bytes).
 /// Slicing is also O(1) - no data is copied, just the view is adjusted.
 ///
 /// # Implementation
@@ -14,6 +19,21 @@ use std::sync::Arc;
 /// This implementation uses `Arc<Vec<u8>>` for shared ownership rather than
 /// raw pointers, ensuring memory safety without unsafe code.
 ///
+/// Payloads of at most [`Bytes::INLINE_CAPACITY`] bytes that are copied or
+/// converted into a `Bytes` are stored inline in the `Bytes` value itself:
+/// no allocation, no reference count. Views created by `slice`, `split_to`
+/// and `split_off` keep whatever representation their source has, so slicing
+/// a large shared buffer never copies.
+///
+/// # Pointer stability
+///
+/// The address returned by `as_ptr()` (via `Deref`) is only stable for
+/// heap-backed and static data. An inline `Bytes` holds its bytes in the
+/// struct, so the pointer changes whenever the value is moved, and clones do
+/// not share an address. Code that needs a stable address across moves (for
+/// example to hand to the kernel) must keep the `Bytes` pinned in place or
+/// copy into its own buffer.
+///
 /// # Examples
 ///
 /// ```
@@ -47,12 +67,20 @@ enum BytesInner {
     Static(&'static [u8]),
     /// Heap-allocated, reference-counted data.
     Shared(Arc<Vec<u8>>),
+    /// Small payload stored in place (no allocation, copied on clone).
+    Inline([u8; INLINE_CAPACITY]),
     /// Empty bytes (no allocation).
     #[default]
     Empty,
 }
 
 impl Bytes {
+    /// Largest payload, in bytes, that is stored inline without allocating.
+    ///
+    /// This is the space the heap representation already uses, so inline
+    /// storage does not make `Bytes` any larger (23 on 64-bit targets).
+    pub const INLINE_CAPACITY: usize = INLINE_CAPACITY;
+
     /// Create an empty `Bytes`.
     ///
     /// No allocation occurs.
@@ -90,7 +118,8 @@ impl Bytes {
 
     /// Copy data from a slice into a new `Bytes`.
     ///
-    /// This allocates and copies the data.
+    /// This copies the data; it allocates only when `data` is longer than
+    /// [`Bytes::INLINE_CAPACITY`].
     ///
     /// # Examples
     ///
@@ -107,6 +136,9 @@ impl Bytes {
         if data.is_empty() {
             return Self::new();
         }
+        if data.len() <= INLINE_CAPACITY {
+            return Self::inline(data);
+        }
         let vec = data.to_vec();
         let len = vec.len();
         Self {
@@ -116,6 +148,18 @@ impl Bytes {
         }
     }
 
+    /// Store `data` (at most `INLINE_CAPACITY` bytes) in place.
+    #[inline]
+    fn inline(data: &[u8]) -> Self {
+        let mut buf = [0; INLINE_CAPACITY];
+        buf[..data.len()].copy_from_slice(data);
+        Self {
+            data: BytesInner::Inline(buf),
+            start: 0,
+            len: data.len(),
+        }
+    }
+
     /// Returns the number of bytes.
     #[inline]
     #[must_use]
@@ -304,6 +348,7 @@ impl Bytes {
             BytesInner::Empty => &[],
             BytesInner::Static(s) => &s[self.start..end],
             BytesInner::Shared(arc) => &arc[self.start..end],
+            BytesInner::Inline(buf) => &buf[self.start..end],
         }
     }
 }
@@ -330,6 +375,9 @@ impl From<Vec<u8>> for Bytes {
         if vec.is_empty() {
             return Self::new();
         }
+        if vec.len() <= INLINE_CAPACITY {
+            return Self::inline(&vec);
+        }
         let len = vec.len();
         Self {
             data: BytesInner::Shared(Arc::new(vec)),
@@ -801,7 +849,8 @@ mod tests {
     fn bytes_cursor_zero_copy_empty_past_end_matches_default() {
         let cases = [
             ("static", Bytes::from_static(b"abc")),
-            ("shared", Bytes::copy_from_slice(b"abc")),
+            ("shared", heap_backed(b"abc")),
+            ("inline", Bytes::copy_from_slice(b"abc")),
             ("empty", Bytes::new()),
         ];
 
@@ -873,6 +922,20 @@ mod tests {
         crate::test_complete!("test_bytes_equality");
     }
 
+    /// Builds a `Bytes` on the shared heap representation regardless of
+    /// length, i.e. what every non-static `Bytes` was before inline storage.
+    fn heap_backed(data: &[u8]) -> Bytes {
+        Bytes {
+            data: BytesInner::Shared(Arc::new(data.to_vec())),
+            start: 0,
+            len: data.len(),
+        }
+    }
+
+    fn is_inline(bytes: &Bytes) -> bool {
+        matches!(bytes.data, BytesInner::Inline(_))
+    }
+
     fn shared_arc_ptr(bytes: &Bytes) -> *const Vec<u8> {
         match &bytes.data {
             BytesInner::Shared(arc) => Arc::as_ptr(arc),
@@ -882,7 +945,7 @@ mod tests {
 
     #[test]
     fn bytes_conformance_clone_preserves_shared_backing_and_full_view() {
-        let original = Bytes::copy_from_slice(b"conformance");
+        let original = heap_backed(b"conformance");
         let clone = original.clone();
 
         assert!(std::ptr::eq(
@@ -899,7 +962,7 @@ mod tests {
 
     #[test]
     fn bytes_conformance_slice_preserves_backing_with_adjusted_offsets() {
-        let original = Bytes::copy_from_slice(b"grpc-frame");
+        let original = heap_backed(b"grpc-frame");
         let slice = original.slice(5..10);
 
         assert!(std::ptr::eq(
@@ -916,7 +979,7 @@ mod tests {
 
     #[test]
     fn bytes_conformance_split_to_keeps_prefix_and_suffix_on_same_backing() {
-        let mut working = Bytes::copy_from_slice(b"wire-format");
+        let mut working = heap_backed(b"wire-format");
         let witness = working.clone();
         let prefix = working.split_to(5);
 
@@ -939,7 +1002,7 @@ mod tests {
 
     #[test]
     fn bytes_conformance_split_off_keeps_tail_view_on_same_backing() {
-        let mut working = Bytes::copy_from_slice(b"task-region");
+        let mut working = heap_backed(b"task-region");
         let witness = working.clone();
         let tail = working.split_off(5);
 
@@ -1039,7 +1102,7 @@ mod tests {
     #[test]
     fn bytes_conformance_clone_shares_data() {
         let data = b"hello world";
-        let original = Bytes::copy_from_slice(data);
+        let original = heap_backed(data);
         let clone1 = original.clone();
         let clone2 = original.clone();
 
@@ -1120,4 +1183,197 @@ mod tests {
         let mut b = Bytes::copy_from_slice(b"hello");
         let _ = b.split_off(10);
     }
+    /// Applies `ops` to a `Bytes` built with `copy_from_slice` (inline when
+    /// small), to a heap-backed `Bytes` (the pre-inline representation) and to
+    /// a `Vec<u8>` model, checking that all three agree after every step.
+    fn assert_matches_model(data: &[u8], ops: &[(u8, usize)]) {
+        let mut subject = Bytes::copy_from_slice(data);
+        let mut heap = heap_backed(data);
+        let mut model = data.to_vec();
+
+        for &(op, arg) in ops {
+            let at = arg % (model.len() + 1);
+            match op % 5 {
+                0 => {
+                    let expected: Vec<u8> = model.drain(..at).collect();
+                    assert_eq!(&subject.split_to(at)[..], &expected[..]);
+                    assert_eq!(&heap.split_to(at)[..], &expected[..]);
+                }
+                1 => {
+                    let expected = model.split_off(at);
+                    assert_eq!(&subject.split_off(at)[..], &expected[..]);
+                    assert_eq!(&heap.split_off(at)[..], &expected[..]);
+                }
+                2 => {
+                    let end = at + (arg / 7) % (model.len() - at + 1);
+                    assert_eq!(&subject.slice(at..end)[..], &model[at..end]);
+                    assert_eq!(&heap.slice(at..end)[..], &model[at..end]);
+                }
+                3 => {
+                    subject.truncate(at);
+                    heap.truncate(at);
+                    model.truncate(at);
+                }
+                _ => {
+                    subject = subject.clone();
+                    heap = heap.clone();
+                }
+            }
+            assert_eq!(&subject[..], model.as_slice());
+            assert_eq!(&heap[..], model.as_slice());
+            assert_eq!(subject, heap);
+            assert_eq!(subject.len(), model.len());
+        }
+    }
+
+    #[test]
+    fn inline_storage_does_not_grow_bytes() {
+        init_test("inline_storage_does_not_grow_bytes");
+        let word = std::mem::size_of::<usize>();
+        let size = std::mem::size_of::<Bytes>();
+        crate::assert_with_log!(size == 5 * word, "size_of::<Bytes>", 5 * word, size);
+        crate::assert_with_log!(
+            Bytes::INLINE_CAPACITY == 3 * word - 1,
+            "inline capacity",
+            3 * word - 1,
+            Bytes::INLINE_CAPACITY
+        );
+        crate::test_complete!("inline_storage_does_not_grow_bytes");
+    }
+
+    #[test]
+    fn inline_boundary_is_exactly_inline_capacity() {
+        init_test("inline_boundary_is_exactly_inline_capacity");
+        let payload: Vec<u8> = (0..=Bytes::INLINE_CAPACITY as u8).collect();
+        let (fits, spills) = (
+            &payload[..Bytes::INLINE_CAPACITY],
+            &payload[..=Bytes::INLINE_CAPACITY],
+        );
+
+        let at_cap = Bytes::copy_from_slice(fits);
+        crate::assert_with_log!(
+            is_inline(&at_cap),
+            "N bytes inline",
+            true,
+            is_inline(&at_cap)
+        );
+        crate::assert_with_log!(&at_cap[..] == fits, "N bytes content", fits, &at_cap[..]);
+        let over_cap = Bytes::copy_from_slice(spills);
+        crate::assert_with_log!(
+            !is_inline(&over_cap),
+            "N+1 bytes shared",
+            false,
+            is_inline(&over_cap)
+        );
+        crate::assert_with_log!(
+            &over_cap[..] == spills,
+            "N+1 bytes content",
+            spills,
+            &over_cap[..]
+        );
+
+        let from_vec = Bytes::from(fits.to_vec());
+        crate::assert_with_log!(
+            is_inline(&from_vec),
+            "vec of N inline",
+            true,
+            is_inline(&from_vec)
+        );
+        let from_vec = Bytes::from(spills.to_vec());
+        crate::assert_with_log!(
+            !is_inline(&from_vec),
+            "vec of N+1 shared",
+            false,
+            is_inline(&from_vec)
+        );
+
+        // Empty and static data keep their allocation-free representations.
+        let empty = is_inline(&Bytes::copy_from_slice(&[]));
+        crate::assert_with_log!(!empty, "empty not inline", false, empty);
+        let stat = is_inline(&Bytes::from_static(b"ack"));
+        crate::assert_with_log!(!stat, "static not inline", false, stat);
+        crate::test_complete!("inline_boundary_is_exactly_inline_capacity");
+    }
+
+    #[test]
+    fn inline_clones_and_views_are_independent_copies() {
+        init_test("inline_clones_and_views_are_independent_copies");
+        let original = Bytes::copy_from_slice(b"HEADERS:ack");
+        let clone = original.clone();
+        crate::assert_with_log!(is_inline(&clone), "clone inline", true, is_inline(&clone));
+        crate::assert_with_log!(clone == original, "clone equal", &original, &clone);
+        let distinct = !std::ptr::eq(clone.as_ptr(), original.as_ptr());
+        crate::assert_with_log!(distinct, "clone has its own address", true, distinct);
+
+        // Moving an inline value moves its bytes (documented pointer caveat).
+        let before_move = original.as_ptr();
+        let moved = Box::new(original);
+        let relocated = !std::ptr::eq(moved.as_ptr(), before_move);
+        crate::assert_with_log!(relocated, "move relocates inline data", true, relocated);
+
+        let mut rest = (*moved).clone();
+        let head = rest.split_to(8);
+        let tail = rest.split_off(1);
+        let view = moved.slice(3..7);
+        for part in [&head, &rest, &tail, &view] {
+            crate::assert_with_log!(is_inline(part), "split part inline", true, is_inline(part));
+        }
+        crate::assert_with_log!(&head[..] == b"HEADERS:", "head", b"HEADERS:", &head[..]);
+        crate::assert_with_log!(&rest[..] == b"a", "rest", b"a", &rest[..]);
+        crate::assert_with_log!(&tail[..] == b"ck", "tail", b"ck", &tail[..]);
+        crate::assert_with_log!(&view[..] == b"DERS", "view", b"DERS", &view[..]);
+
+        // A small view of a shared buffer stays a zero-copy view.
+        let shared = Bytes::copy_from_slice(&[7u8; 64]);
+        let small = shared.slice(0..4);
+        crate::assert_with_log!(
+            !is_inline(&small),
+            "shared view stays shared",
+            false,
+            is_inline(&small)
+        );
+        let same_backing = std::ptr::eq(shared_arc_ptr(&shared), shared_arc_ptr(&small));
+        crate::assert_with_log!(
+            same_backing,
+            "shared view keeps backing",
+            true,
+            same_backing
+        );
+        crate::test_complete!("inline_clones_and_views_are_independent_copies");
+    }
+
+    #[test]
+    fn inline_matches_heap_model_around_boundary() {
+        init_test("inline_matches_heap_model_around_boundary");
+        let ops: &[&[(u8, usize)]] = &[
+            &[(0, 3), (1, 5), (4, 0), (2, 9)],
+            &[(1, 1), (0, 1), (3, 2), (4, 0), (0, 100)],
+            &[(2, 40), (4, 0), (0, 11), (1, 0), (3, 0)],
+            &[(3, 17), (0, 6), (2, 1), (1, 3)],
+        ];
+        for len in [
+            0,
+            1,
+            Bytes::INLINE_CAPACITY - 1,
+            Bytes::INLINE_CAPACITY,
+            Bytes::INLINE_CAPACITY + 1,
+            64,
+        ] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 31 + 7) as u8).collect();
+            for sequence in ops {
+                assert_matches_model(&data, sequence);
+            }
+        }
+        crate::test_complete!("inline_matches_heap_model_around_boundary");
+    }
+
+    proptest! {
+        #[test]
+        fn inline_representation_is_transparent(
+            data in prop::collection::vec(any::<u8>(), 0..48),
+            ops in prop::collection::vec((any::<u8>(), any::<usize>()), 0..16),
+        ) {
+            assert_matches_model(&data, &ops);
+        }
+    }
 }
@@ -18,6 +18,13 @@ use std::ops::{Deref, DerefMut, RangeBounds};
 /// distinct `Vec<u8>` storage, preserving mutable independence without shared
 /// mutable backing.
 ///
+/// Buffers that start empty (`new`, `with_capacity(0)`) or small (`From<&[u8]>`,
+/// small split-out parts) hold up to [`BytesMut::INLINE_CAPACITY`] bytes inline
+/// in the `BytesMut` value, without allocating. The first write that needs more
+/// room moves the contents to a heap `Vec<u8>`; this is invisible apart from
+/// `capacity()` and from the data's address, which changes when an inline
+/// buffer is moved or promoted.
+///
 /// # Examples
 ///
 /// ```
@@ -33,13 +40,235 @@ use std::ops::{Deref, DerefMut, RangeBounds};
 #[derive(Clone, Default)]
 pub struct BytesMut {
     /// The backing storage.
-    data: Vec<u8>,
+    data: Storage,
     /// Start offset of the active byte range in `data`.
     start: usize,
 }
 
+/// Largest inline payload: what fits beside the discriminant without making
+/// `Storage` larger than `Vec<u8>` (15 on 64-bit targets).
+const INLINE_CAPACITY: usize = 2 * std::mem::size_of::<usize>() - 1;
+
+/// Backing storage for [`BytesMut`]: a small in-place buffer that is promoted
+/// to a `Vec<u8>` on demand.
+///
+/// Mirrors the subset of the `Vec<u8>` API that `BytesMut` uses, so the
+/// representation switch stays out of the offset bookkeeping above it.
+#[derive(Clone)]
+enum Storage {
+    /// Up to `INLINE_CAPACITY` bytes stored in place.
+    Inline { len: u8, buf: [u8; INLINE_CAPACITY] },
+    /// Heap buffer, used once the contents outgrow the inline space.
+    Heap(Vec<u8>),
+}
+
+impl Default for Storage {
+    #[inline]
+    fn default() -> Self {
+        Self::Inline {
+            len: 0,
+            buf: [0; INLINE_CAPACITY],
+        }
+    }
+}
+
+impl Storage {
+    #[inline]
+    fn from_slice(data: &[u8]) -> Self {
+        if data.len() > INLINE_CAPACITY {
+            return Self::Heap(data.to_vec());
+        }
+        let mut buf = [0; INLINE_CAPACITY];
+        buf[..data.len()].copy_from_slice(data);
+        Self::Inline {
+            len: inline_len(data.len()),
+            buf,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Self::Inline { len, .. } => usize::from(*len),
+            Self::Heap(vec) => vec.len(),
+        }
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Inline { .. } => INLINE_CAPACITY,
+            Self::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    #[inline]
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Inline { len, buf } => &buf[..usize::from(*len)],
+            Self::Heap(vec) => vec,
+        }
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            Self::Inline { len, buf } => &mut buf[..usize::from(*len)],
+            Self::Heap(vec) => vec,
+        }
+    }
+
+    /// Moves inline contents to a heap `Vec` with at least `capacity` bytes.
+    #[inline]
+    fn promote(&mut self, capacity: usize) -> &mut Vec<u8> {
+        if let Self::Inline { len, buf } = self {
+            let len = usize::from(*len);
+            let mut vec = Vec::with_capacity(capacity.max(len));
+            vec.extend_from_slice(&buf[..len]);
+            *self = Self::Heap(vec);
+        }
+        match self {
+            Self::Heap(vec) => vec,
+            Self::Inline { .. } => unreachable!("storage was just promoted"),
+        }
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("BytesMut required capacity overflow");
+        match self {
+            Self::Inline { .. } if required <= INLINE_CAPACITY => {}
+            // Leave headroom so a promoted buffer does not regrow immediately.
+            Self::Inline { .. } => {
+                self.promote(required.max(2 * INLINE_CAPACITY));
+            }
+            Self::Heap(vec) => vec.reserve(additional),
+        }
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, src: &[u8]) {
+        self.reserve(src.len());
+        match self {
+            Self::Inline { len, buf } => {
+                let start = usize::from(*len);
+                let end = start + src.len();
+                buf[start..end].copy_from_slice(src);
+                *len = inline_len(end);
+            }
+            Self::Heap(vec) => vec.extend_from_slice(src),
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, value: u8) {
+        self.extend_from_slice(&[value]);
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        match self {
+            Self::Inline { len, .. } => {
+                if new_len < usize::from(*len) {
+                    *len = inline_len(new_len);
+                }
+            }
+            Self::Heap(vec) => vec.truncate(new_len),
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        let old_len = self.len();
+        if new_len <= old_len {
+            self.truncate(new_len);
+            return;
+        }
+        self.reserve(new_len - old_len);
+        match self {
+            Self::Inline { len, buf } => {
+                buf[old_len..new_len].fill(value);
+                *len = inline_len(new_len);
+            }
+            Self::Heap(vec) => vec.resize(new_len, value),
+        }
+    }
+
+    /// Removes the first `count` bytes, shifting the rest to the front.
+    #[inline]
+    fn drain_front(&mut self, count: usize) {
+        match self {
+            Self::Inline { len, buf } => {
+                let old_len = usize::from(*len);
+                buf.copy_within(count..old_len, 0);
+                *len = inline_len(old_len - count);
+            }
+            Self::Heap(vec) => {
+                vec.drain(..count);
+            }
+        }
+    }
+
+    /// Splits off `[at, len)`. Small tails come back inline, so splitting a
+    /// short trailer off a heap buffer does not allocate.
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        if let Self::Heap(vec) = self
+            && (at == 0 || vec.len() - at > INLINE_CAPACITY)
+        {
+            return Self::Heap(vec.split_off(at));
+        }
+        let tail = Self::from_slice(&self.as_slice()[at..]);
+        self.truncate(at);
+        tail
+    }
+
+    #[inline]
+    fn spare_capacity_mut(&mut self) -> &mut [std::mem::MaybeUninit<u8>] {
+        // Inline bytes are always initialized; exposing them as `MaybeUninit`
+        // would need `unsafe`, so hand out a heap buffer's spare capacity.
+        self.promote(INLINE_CAPACITY).spare_capacity_mut()
+    }
+
+    #[inline]
+    fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Inline { len, buf } => buf[..usize::from(len)].to_vec(),
+            Self::Heap(vec) => vec,
+        }
+    }
+
+    #[inline]
+    fn into_bytes(self) -> Bytes {
+        match self {
+            Self::Inline { len, buf } => Bytes::copy_from_slice(&buf[..usize::from(len)]),
+            Self::Heap(vec) => Bytes::from(vec),
+        }
+    }
+}
+
+#[inline]
+fn inline_len(len: usize) -> u8 {
+    u8::try_from(len).expect("inline BytesMut length exceeds u8")
+}
+
 impl BytesMut {
+    /// Largest payload, in bytes, that a `BytesMut` holds inline before its
+    /// first heap allocation (15 on 64-bit targets).
+    pub const INLINE_CAPACITY: usize = INLINE_CAPACITY;
+
     /// Create an empty `BytesMut`.
+    ///
+    /// No allocation occurs until more than [`BytesMut::INLINE_CAPACITY`]
+    /// bytes are written.
     #[inline]
     #[must_use]
     pub fn new() -> Self {
@@ -48,6 +277,9 @@ impl BytesMut {
 
     /// Create a `BytesMut` with the given capacity.
     ///
+    /// A non-zero `capacity` is allocated on the heap up front, exactly as
+    /// requested; `with_capacity(0)` is the same as [`new`](Self::new).
+    ///
     /// # Examples
     ///
     /// ```
@@ -60,20 +292,24 @@ impl BytesMut {
     #[inline]
     #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
+        if capacity == 0 {
+            return Self::new();
+        }
         Self {
-            data: Vec::with_capacity(capacity),
+            data: Storage::Heap(Vec::with_capacity(capacity)),
             start: 0,
         }
     }
 
     #[inline]
     fn active(&self) -> &[u8] {
-        &self.data[self.start..]
+        &self.data.as_slice()[self.start..]
     }
 
     #[inline]
     fn active_mut(&mut self) -> &mut [u8] {
-        &mut self.data[self.start..]
+        let start = self.start;
+        &mut self.data.as_mut_slice()[start..]
     }
 
     #[inline]
@@ -86,7 +322,7 @@ impl BytesMut {
         if self.start == 0 {
             return;
         }
-        self.data.drain(..self.start);
+        self.data.drain_front(self.start);
         self.start = 0;
     }
 
@@ -157,7 +393,7 @@ impl BytesMut {
     #[must_use]
     pub fn freeze(mut self) -> Bytes {
         self.compact_front();
-        Bytes::from(self.data)
+        self.data.into_bytes()
     }
 
     /// Consume `self` and return the underlying `Vec<u8>` WITHOUT copying.
@@ -174,7 +410,9 @@ impl BytesMut {
     /// Note: in the current `Vec<u8>`-backed `BytesMut` representation,
     /// every `BytesMut` exclusively owns its underlying `Vec<u8>` (no
     /// shared backing — see `split_to` doc), so this conversion is
-    /// always safe and zero-cost. If a future refactor introduces
+    /// always safe and zero-cost. The one exception is a buffer still
+    /// stored inline (at most [`BytesMut::INLINE_CAPACITY`] bytes), which
+    /// is copied into a fresh `Vec<u8>`. If a future refactor introduces
     /// shared backing storage for `BytesMut` (mirroring `Bytes`), this
     /// method may need to clone in the shared case to preserve the
     /// `Vec<u8>` exclusive-ownership contract — but the API contract
@@ -183,7 +421,7 @@ impl BytesMut {
     #[must_use]
     pub fn into_vec(mut self) -> Vec<u8> {
         self.compact_front();
-        self.data
+        self.data.into_vec()
     }
 
     /// Reserve at least `additional` more bytes of capacity.
@@ -281,7 +519,9 @@ impl BytesMut {
     ///
     /// This is O(n) in the returned prefix length: the prefix is copied so the
     /// returned `BytesMut` remains mutably independent, while `self` advances
-    /// its active start offset instead of moving the remaining suffix.
+    /// its active start offset instead of moving the remaining suffix. A
+    /// prefix of at most [`BytesMut::INLINE_CAPACITY`] bytes is returned
+    /// inline, without allocating.
     ///
     /// # Panics
     ///
@@ -308,8 +548,7 @@ impl BytesMut {
             self.len()
         );
 
-        let mut head = Vec::with_capacity(at);
-        head.extend_from_slice(&self.active()[..at]);
+        let head = Storage::from_slice(&self.active()[..at]);
 
         self.start = self
             .start
@@ -449,10 +688,13 @@ impl BytesMut {
             .checked_add(end)
             .expect("BytesMut::slice end offset overflow");
 
-        &self.data[start..end]
+        &self.data.as_slice()[start..end]
     }
 
     /// Returns the remaining spare capacity as a mutable slice.
+    ///
+    /// An inline buffer is moved to the heap first (keeping its capacity),
+    /// since inline storage cannot be exposed as uninitialized memory.
     #[must_use]
     #[inline]
     pub fn spare_capacity_mut(&mut self) -> &mut [std::mem::MaybeUninit<u8>] {
@@ -518,7 +760,7 @@ impl From<Vec<u8>> for BytesMut {
     #[inline]
     fn from(vec: Vec<u8>) -> Self {
         Self {
-            data: vec,
+            data: Storage::Heap(vec),
             start: 0,
         }
     }
@@ -528,7 +770,7 @@ impl From<&[u8]> for BytesMut {
     #[inline]
     fn from(slice: &[u8]) -> Self {
         Self {
-            data: slice.to_vec(),
+            data: Storage::from_slice(slice),
             start: 0,
         }
     }
@@ -1252,4 +1494,218 @@ mod tests {
         let mut b = BytesMut::with_capacity(16);
         b.advance_mut(1);
     }
+    fn is_inline(buf: &BytesMut) -> bool {
+        matches!(buf.data, Storage::Inline { .. })
+    }
+
+    /// Applies `ops` to a `BytesMut` built from a slice (inline when small),
+    /// to one forced onto a heap `Vec` (the pre-inline representation) and to
+    /// a `Vec<u8>` model, checking that all three agree after every step.
+    fn assert_matches_model(data: &[u8], ops: &[(u8, usize)]) {
+        let mut subject = BytesMut::from(data);
+        let mut heap = BytesMut::from(data.to_vec());
+        let mut model = data.to_vec();
+
+        for &(op, arg) in ops {
+            let at = arg % (model.len() + 1);
+            match op % 8 {
+                0 => {
+                    let chunk: Vec<u8> = (0..arg % 24).map(|i| (i ^ arg) as u8).collect();
+                    subject.put_slice(&chunk);
+                    heap.put_slice(&chunk);
+                    model.extend_from_slice(&chunk);
+                }
+                1 => {
+                    let expected: Vec<u8> = model.drain(..at).collect();
+                    assert_eq!(&subject.split_to(at)[..], &expected[..]);
+                    assert_eq!(&heap.split_to(at)[..], &expected[..]);
+                }
+                2 => {
+                    let expected = model.split_off(at);
+                    assert_eq!(&subject.split_off(at)[..], &expected[..]);
+                    assert_eq!(&heap.split_off(at)[..], &expected[..]);
+                }
+                3 => {
+                    subject.advance(at);
+                    heap.advance(at);
+                    model.drain(..at);
+                }
+                4 => {
+                    let new_len = arg % 40;
+                    subject.resize(new_len, 0xA5);
+                    heap.resize(new_len, 0xA5);
+                    model.resize(new_len, 0xA5);
+                }
+                5 => {
+                    subject.truncate(at);
+                    heap.truncate(at);
+                    model.truncate(at);
+                }
+                6 => {
+                    subject.reserve(arg % 32);
+                    heap.reserve(arg % 32);
+                    let ok = subject.capacity() >= subject.len() + arg % 32;
+                    assert!(ok, "reserve must guarantee the requested room");
+                }
+                _ => {
+                    assert_eq!(&subject.clone().freeze()[..], model.as_slice());
+                    assert_eq!(heap.clone().into_vec(), model);
+                }
+            }
+            assert_eq!(&subject[..], model.as_slice());
+            assert_eq!(&heap[..], model.as_slice());
+            assert_eq!(subject.len(), model.len());
+        }
+    }
+
+    #[test]
+    fn inline_storage_does_not_grow_bytes_mut() {
+        init_test("inline_storage_does_not_grow_bytes_mut");
+        let expected = std::mem::size_of::<Vec<u8>>() + std::mem::size_of::<usize>();
+        let size = std::mem::size_of::<BytesMut>();
+        crate::assert_with_log!(size == expected, "size_of::<BytesMut>", expected, size);
+        crate::test_complete!("inline_storage_does_not_grow_bytes_mut");
+    }
+
+    #[test]
+    fn inline_promotes_on_growth_past_capacity() {
+        init_test("inline_promotes_on_growth_past_capacity");
+        let payload: Vec<u8> = (0..=BytesMut::INLINE_CAPACITY as u8).collect();
+        let mut b = BytesMut::new();
+        b.put_slice(&payload[..BytesMut::INLINE_CAPACITY]);
+        crate::assert_with_log!(is_inline(&b), "N bytes stay inline", true, is_inline(&b));
+        let cap = b.capacity();
+        crate::assert_with_log!(
+            cap == BytesMut::INLINE_CAPACITY,
+            "inline capacity",
+            BytesMut::INLINE_CAPACITY,
+            cap
+        );
+
+        b.put_u8(payload[BytesMut::INLINE_CAPACITY]);
+        crate::assert_with_log!(!is_inline(&b), "N+1 bytes promoted", false, is_inline(&b));
+        crate::assert_with_log!(b[..] == payload[..], "contents kept", &payload[..], &b[..]);
+        let grown = b.capacity() >= 2 * BytesMut::INLINE_CAPACITY;
+        crate::assert_with_log!(grown, "promotion leaves headroom", true, grown);
+
+        // Explicit capacity requests go straight to the heap.
+        let sized = BytesMut::with_capacity(4);
+        crate::assert_with_log!(
+            !is_inline(&sized),
+            "with_capacity heap",
+            false,
+            is_inline(&sized)
+        );
+        let empty = BytesMut::with_capacity(0);
+        crate::assert_with_log!(
+            is_inline(&empty),
+            "with_capacity(0) inline",
+            true,
+            is_inline(&empty)
+        );
+
+        // Spare capacity is only handed out from a heap buffer.
+        let mut spare = BytesMut::from(&b"abc"[..]);
+        let room = spare.spare_capacity_mut().len();
+        crate::assert_with_log!(
+            !is_inline(&spare),
+            "spare capacity promotes",
+            false,
+            is_inline(&spare)
+        );
+        let room_ok = room >= BytesMut::INLINE_CAPACITY - 3;
+        crate::assert_with_log!(room_ok, "spare room kept", true, room_ok);
+        crate::test_complete!("inline_promotes_on_growth_past_capacity");
+    }
+
+    #[test]
+    fn small_split_parts_come_back_inline() {
+        init_test("small_split_parts_come_back_inline");
+        let frame: Vec<u8> = (0..64u8).collect();
+        let mut b = BytesMut::from(frame.clone());
+
+        let header = b.split_to(4);
+        crate::assert_with_log!(
+            is_inline(&header),
+            "small head inline",
+            true,
+            is_inline(&header)
+        );
+        let trailer = b.split_off(b.len() - 8);
+        crate::assert_with_log!(
+            is_inline(&trailer),
+            "small tail inline",
+            true,
+            is_inline(&trailer)
+        );
+        crate::assert_with_log!(!is_inline(&b), "large rest on heap", false, is_inline(&b));
+        let body = b.split_to(BytesMut::INLINE_CAPACITY + 1);
+        crate::assert_with_log!(
+            !is_inline(&body),
+            "large head on heap",
+            false,
+            is_inline(&body)
+        );
+        let rest = b.split_off(0);
+        crate::assert_with_log!(
+            !is_inline(&rest),
+            "large tail on heap",
+            false,
+            is_inline(&rest)
+        );
+
+        crate::assert_with_log!(header[..] == frame[..4], "header", &frame[..4], &header[..]);
+        crate::assert_with_log!(
+            trailer[..] == frame[56..],
+            "trailer",
+            &frame[56..],
+            &trailer[..]
+        );
+        crate::assert_with_log!(body[..] == frame[4..20], "body", &frame[4..20], &body[..]);
+        crate::assert_with_log!(rest[..] == frame[20..56], "rest", &frame[20..56], &rest[..]);
+
+        // Splitting an inline buffer keeps both halves inline.
+        let mut small = BytesMut::from(&b"GET /ok"[..]);
+        let path = small.split_off(4);
+        crate::assert_with_log!(is_inline(&small), "inline head", true, is_inline(&small));
+        crate::assert_with_log!(is_inline(&path), "inline tail", true, is_inline(&path));
+        crate::assert_with_log!(&small[..] == b"GET ", "method", b"GET ", &small[..]);
+        crate::assert_with_log!(&path[..] == b"/ok", "path", b"/ok", &path[..]);
+        crate::test_complete!("small_split_parts_come_back_inline");
+    }
+
+    #[test]
+    fn inline_matches_heap_model_around_boundary() {
+        init_test("inline_matches_heap_model_around_boundary");
+        let ops: &[&[(u8, usize)]] = &[
+            &[(0, 1), (0, 23), (1, 3), (2, 9), (7, 0)],
+            &[(4, 15), (0, 1), (3, 4), (4, 16), (7, 0), (1, 100)],
+            &[(2, 5), (0, 10), (6, 40), (5, 2), (7, 0), (0, 16)],
+            &[(3, 1), (4, 39), (2, 15), (1, 15), (7, 0)],
+        ];
+        for len in [
+            0,
+            1,
+            BytesMut::INLINE_CAPACITY - 1,
+            BytesMut::INLINE_CAPACITY,
+            BytesMut::INLINE_CAPACITY + 1,
+            64,
+        ] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 13 + 5) as u8).collect();
+            for sequence in ops {
+                assert_matches_model(&data, sequence);
+            }
+        }
+        crate::test_complete!("inline_matches_heap_model_around_boundary");
+    }
+
+    proptest! {
+        #[test]
+        fn inline_representation_is_transparent(
+            data in prop::collection::vec(any::<u8>(), 0..48),
+            ops in prop::collection::vec((any::<u8>(), any::<usize>()), 0..24),
+        ) {
+            assert_matches_model(&data, &ops);
+        }
+    }
 }
@@ -18,6 +18,13 @@
 //! trades some performance for safety, alignment with asupersync's
 //! `#![forbid(unsafe_code)]` policy, and simplicity.
 //!
+//! Small payloads skip the heap entirely: `Bytes` stores up to
+//! [`Bytes::INLINE_CAPACITY`] bytes and `BytesMut` up to
+//! [`BytesMut::INLINE_CAPACITY`] bytes in place, in space the types already
+//! occupy. Growing past that moves the data to the heap. Apart from capacity
+//! and data addresses (an inline value's bytes move with it), the two
+//! representations behave identically.
+//!
 //! # Cancel-Safety
 //!
 //! Buffer operations are synchronous and thus inherently cancel-safe.
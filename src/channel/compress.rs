@@ -0,0 +1,495 @@
+//! Payload-compressing channel wrappers.
+//!
+//! [`CompressedSender`] and [`CompressedReceiver`] carry byte payloads over an
+//! [`mpsc`] channel of [`CompressedPayload`]s; [`CompressedBroadcastSender`]
+//! and [`CompressedBroadcastReceiver`] do the same over [`broadcast`]. The
+//! sender compresses each payload with a [`PayloadCompressor`] when it
+//! commits to a reserved slot, so a closed or cancelled send never pays for
+//! compression, and the receiver inflates it under its own decompression
+//! bound. Broadcast payloads
+//! are compressed once and shared by every receiver.
+//!
+//! Whether a payload is compressed depends only on its size and the
+//! compressor configuration, so lab replays see the same encoded values.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::channel::compress;
+//! use asupersync::codec::{CompressionConfig, PayloadCompressor};
+//!
+//! let compressor = PayloadCompressor::new(CompressionConfig::lz4())?;
+//! let (tx, mut rx) = compress::channel(16, compressor);
+//!
+//! tx.send(&cx, large_payload).await?;
+//! let payload = rx.recv(&cx).await?;
+//! ```
+
+use std::fmt;
+
+use crate::channel::{broadcast, mpsc};
+use crate::codec::compression::{CompressedPayload, CompressionError, PayloadCompressor};
+use crate::cx::Cx;
+
+/// Error returned when receiving a compressed payload fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompressedRecvError<E> {
+    /// The underlying channel failed.
+    Channel(E),
+    /// The payload could not be inflated.
+    Payload(CompressionError),
+}
+
+impl<E: fmt::Display> fmt::Display for CompressedRecvError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Channel(err) => fmt::Display::fmt(err, f),
+            Self::Payload(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display + 'static> std::error::Error for CompressedRecvError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Channel(_) => None,
+            Self::Payload(err) => Some(err),
+        }
+    }
+}
+
+fn mpsc_error_with<T, U>(err: mpsc::SendError<T>, value: U) -> mpsc::SendError<U> {
+    match err {
+        mpsc::SendError::Disconnected(_) => mpsc::SendError::Disconnected(value),
+        mpsc::SendError::Cancelled(_) => mpsc::SendError::Cancelled(value),
+        mpsc::SendError::Full(_) => mpsc::SendError::Full(value),
+    }
+}
+
+fn broadcast_error_with<T, U>(err: broadcast::SendError<T>, value: U) -> broadcast::SendError<U> {
+    match err {
+        broadcast::SendError::Closed(_) => broadcast::SendError::Closed(value),
+        broadcast::SendError::Cancelled(_) => broadcast::SendError::Cancelled(value),
+    }
+}
+
+/// Creates a bounded MPSC channel whose payloads are compressed above the
+/// compressor's threshold. Both ends share `compressor`'s metrics.
+#[must_use]
+pub fn channel(
+    capacity: usize,
+    compressor: PayloadCompressor,
+) -> (CompressedSender, CompressedReceiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+    (
+        CompressedSender::new(tx, compressor.clone()),
+        CompressedReceiver::new(rx, compressor),
+    )
+}
+
+/// Creates a broadcast channel whose payloads are compressed above the
+/// compressor's threshold. Both ends share `compressor`'s metrics.
+#[must_use]
+pub fn broadcast_channel(
+    capacity: usize,
+    compressor: PayloadCompressor,
+) -> (CompressedBroadcastSender, CompressedBroadcastReceiver) {
+    let (tx, rx) = broadcast::channel(capacity);
+    (
+        CompressedBroadcastSender::new(tx, compressor.clone()),
+        CompressedBroadcastReceiver::new(rx, compressor),
+    )
+}
+
+/// Sending half of a compressing MPSC channel.
+#[derive(Debug, Clone)]
+pub struct CompressedSender {
+    inner: mpsc::Sender<CompressedPayload>,
+    compressor: PayloadCompressor,
+}
+
+impl CompressedSender {
+    /// Wraps an MPSC sender.
+    #[must_use]
+    pub fn new(inner: mpsc::Sender<CompressedPayload>, compressor: PayloadCompressor) -> Self {
+        Self { inner, compressor }
+    }
+
+    /// Waits for a slot and returns a permit that compresses at commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the receiver is gone or the reservation is
+    /// cancelled.
+    pub async fn reserve<'a>(
+        &'a self,
+        cx: &'a Cx,
+    ) -> Result<CompressedPermit<'a>, mpsc::SendError<()>> {
+        let permit = self.inner.reserve(cx).await?;
+        Ok(CompressedPermit {
+            permit,
+            compressor: &self.compressor,
+        })
+    }
+
+    /// Waits for a slot, then compresses and commits `payload`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the payload if the receiver is gone or the send is cancelled.
+    pub async fn send(&self, cx: &Cx, payload: Vec<u8>) -> Result<(), mpsc::SendError<Vec<u8>>> {
+        match self.reserve(cx).await {
+            Ok(permit) => permit.send(payload),
+            Err(err) => Err(mpsc_error_with(err, payload)),
+        }
+    }
+
+    /// Compresses and commits `payload` if a slot is free right now.
+    ///
+    /// # Errors
+    ///
+    /// Returns the payload if the channel is full or the receiver is gone.
+    pub fn try_send(&self, payload: Vec<u8>) -> Result<(), mpsc::SendError<Vec<u8>>> {
+        match self.inner.try_reserve() {
+            Ok(permit) => CompressedPermit {
+                permit,
+                compressor: &self.compressor,
+            }
+            .send(payload),
+            Err(err) => Err(mpsc_error_with(err, payload)),
+        }
+    }
+
+    /// Returns the compressor.
+    #[must_use]
+    pub fn compressor(&self) -> &PayloadCompressor {
+        &self.compressor
+    }
+
+    /// Returns the underlying sender.
+    #[must_use]
+    pub fn inner(&self) -> &mpsc::Sender<CompressedPayload> {
+        &self.inner
+    }
+}
+
+/// A reserved slot in a compressing MPSC channel.
+///
+/// Dropping the permit releases the slot without sending.
+#[derive(Debug)]
+#[must_use = "CompressedPermit must be consumed via send() or abort()"]
+pub struct CompressedPermit<'a> {
+    permit: mpsc::SendPermit<'a, CompressedPayload>,
+    compressor: &'a PayloadCompressor,
+}
+
+impl CompressedPermit<'_> {
+    /// Compresses `payload` and commits it to the reserved slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns the payload if the receiver was dropped.
+    pub fn send(self, payload: Vec<u8>) -> Result<(), mpsc::SendError<Vec<u8>>> {
+        self.permit
+            .try_send(self.compressor.compress(&payload))
+            .map_err(|err| mpsc_error_with(err, payload))
+    }
+
+    /// Releases the slot without sending.
+    pub fn abort(self) {
+        self.permit.abort();
+    }
+}
+
+/// Receiving half of a compressing MPSC channel.
+#[derive(Debug)]
+pub struct CompressedReceiver {
+    inner: mpsc::Receiver<CompressedPayload>,
+    compressor: PayloadCompressor,
+}
+
+impl CompressedReceiver {
+    /// Wraps an MPSC receiver. `compressor` supplies the decompression bound.
+    #[must_use]
+    pub fn new(inner: mpsc::Receiver<CompressedPayload>, compressor: PayloadCompressor) -> Self {
+        Self { inner, compressor }
+    }
+
+    /// Receives and inflates the next payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressedRecvError::Channel`] if the channel is closed or
+    /// the receive is cancelled, and [`CompressedRecvError::Payload`] if the
+    /// payload exceeds the decompression bound or is corrupt.
+    pub async fn recv(&mut self, cx: &Cx) -> Result<Vec<u8>, CompressedRecvError<mpsc::RecvError>> {
+        let payload = self
+            .inner
+            .recv(cx)
+            .await
+            .map_err(CompressedRecvError::Channel)?;
+        self.compressor
+            .decompress(&payload)
+            .map_err(CompressedRecvError::Payload)
+    }
+
+    /// Receives and inflates a payload if one is queued.
+    ///
+    /// # Errors
+    ///
+    /// As [`recv`](Self::recv), with [`mpsc::RecvError::Empty`] when nothing
+    /// is queued.
+    pub fn try_recv(&mut self) -> Result<Vec<u8>, CompressedRecvError<mpsc::RecvError>> {
+        let payload = self.inner.try_recv().map_err(CompressedRecvError::Channel)?;
+        self.compressor
+            .decompress(&payload)
+            .map_err(CompressedRecvError::Payload)
+    }
+
+    /// Returns the compressor.
+    #[must_use]
+    pub fn compressor(&self) -> &PayloadCompressor {
+        &self.compressor
+    }
+}
+
+/// Sending half of a compressing broadcast channel.
+#[derive(Debug, Clone)]
+pub struct CompressedBroadcastSender {
+    inner: broadcast::Sender<CompressedPayload>,
+    compressor: PayloadCompressor,
+}
+
+impl CompressedBroadcastSender {
+    /// Wraps a broadcast sender.
+    #[must_use]
+    pub fn new(inner: broadcast::Sender<CompressedPayload>, compressor: PayloadCompressor) -> Self {
+        Self { inner, compressor }
+    }
+
+    /// Compresses `payload` once and delivers it to every receiver.
+    ///
+    /// Returns the number of receivers that were live at commit time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the payload if every receiver is gone or `cx` is cancelled.
+    pub fn send(&self, cx: &Cx, payload: Vec<u8>) -> Result<usize, broadcast::SendError<Vec<u8>>> {
+        let permit = match self.inner.reserve(cx) {
+            Ok(permit) => permit,
+            Err(err) => return Err(broadcast_error_with(err, payload)),
+        };
+        Ok(permit.send(self.compressor.compress(&payload)))
+    }
+
+    /// Creates a receiver that sees payloads sent after this call.
+    #[must_use]
+    pub fn subscribe(&self) -> CompressedBroadcastReceiver {
+        CompressedBroadcastReceiver::new(self.inner.subscribe(), self.compressor.clone())
+    }
+
+    /// Returns the compressor.
+    #[must_use]
+    pub fn compressor(&self) -> &PayloadCompressor {
+        &self.compressor
+    }
+}
+
+/// Receiving half of a compressing broadcast channel.
+#[derive(Debug)]
+pub struct CompressedBroadcastReceiver {
+    inner: broadcast::Receiver<CompressedPayload>,
+    compressor: PayloadCompressor,
+}
+
+impl CompressedBroadcastReceiver {
+    /// Wraps a broadcast receiver. `compressor` supplies the decompression
+    /// bound.
+    #[must_use]
+    pub fn new(
+        inner: broadcast::Receiver<CompressedPayload>,
+        compressor: PayloadCompressor,
+    ) -> Self {
+        Self { inner, compressor }
+    }
+
+    /// Receives and inflates the next payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressedRecvError::Channel`] if the receiver lagged, the
+    /// channel closed or the receive was cancelled, and
+    /// [`CompressedRecvError::Payload`] if the payload exceeds the
+    /// decompression bound or is corrupt.
+    pub async fn recv(
+        &mut self,
+        cx: &Cx,
+    ) -> Result<Vec<u8>, CompressedRecvError<broadcast::RecvError>> {
+        let payload = self
+            .inner
+            .recv(cx)
+            .await
+            .map_err(CompressedRecvError::Channel)?;
+        self.compressor
+            .decompress(&payload)
+            .map_err(CompressedRecvError::Payload)
+    }
+
+    /// Receives and inflates a payload if one is queued.
+    ///
+    /// # Errors
+    ///
+    /// As [`recv`](Self::recv), with [`broadcast::TryRecvError::Empty`] when
+    /// nothing is queued.
+    pub fn try_recv(&mut self) -> Result<Vec<u8>, CompressedRecvError<broadcast::TryRecvError>> {
+        let payload = self.inner.try_recv().map_err(CompressedRecvError::Channel)?;
+        self.compressor
+            .decompress(&payload)
+            .map_err(CompressedRecvError::Payload)
+    }
+
+    /// Returns the compressor.
+    #[must_use]
+    pub fn compressor(&self) -> &PayloadCompressor {
+        &self.compressor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::codec::compression::{CompressionAlgorithm, CompressionConfig};
+    use std::future::Future;
+    use std::task::{Context, Poll};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let waker = std::task::Waker::noop().clone();
+        let mut cx = Context::from_waker(&waker);
+        let mut pinned = Box::pin(f);
+        loop {
+            match pinned.as_mut().poll(&mut cx) {
+                Poll::Ready(v) => return v,
+                Poll::Pending => std::thread::yield_now(),
+            }
+        }
+    }
+
+    fn compressor() -> PayloadCompressor {
+        PayloadCompressor::new(CompressionConfig::default().with_threshold(1024))
+            .expect("default config is valid")
+    }
+
+    fn large_payload() -> Vec<u8> {
+        b"structured concurrency "
+            .iter()
+            .copied()
+            .cycle()
+            .take(16 * 1024)
+            .collect()
+    }
+
+    #[test]
+    fn mpsc_round_trips_payloads_on_both_sides_of_threshold() {
+        init_test("mpsc_round_trips_payloads_on_both_sides_of_threshold");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = channel(4, compressor());
+
+        block_on(tx.send(&cx, b"small".to_vec())).expect("send small");
+        block_on(tx.send(&cx, large_payload())).expect("send large");
+
+        let small = block_on(rx.recv(&cx)).expect("recv small");
+        crate::assert_with_log!(small == b"small", "small payload", "small", small.len());
+        let large = block_on(rx.recv(&cx)).expect("recv large");
+        crate::assert_with_log!(
+            large == large_payload(),
+            "large payload",
+            large_payload().len(),
+            large.len()
+        );
+
+        let metrics = rx.compressor().metrics();
+        let expected = u64::from(CompressionAlgorithm::Lz4.is_available());
+        crate::assert_with_log!(
+            metrics.compressed == expected,
+            "compressed count",
+            expected,
+            metrics.compressed
+        );
+        crate::assert_with_log!(
+            metrics.decompressed == expected,
+            "inflated count",
+            expected,
+            metrics.decompressed
+        );
+        crate::test_complete!("mpsc_round_trips_payloads_on_both_sides_of_threshold");
+    }
+
+    #[test]
+    fn mpsc_receiver_rejects_oversized_payload() {
+        init_test("mpsc_receiver_rejects_oversized_payload");
+        let (tx, rx) = mpsc::channel(1);
+        let mut rx = CompressedReceiver::new(rx, compressor());
+        let mut frame = vec![CompressionAlgorithm::Lz4.tag()];
+        frame.extend_from_slice(&u64::MAX.to_le_bytes());
+        let forged = CompressedPayload::decode(&frame).expect("well-formed header");
+        tx.try_send(forged).expect("queue forged payload");
+
+        let result = rx.try_recv();
+        let rejected = matches!(
+            result,
+            Err(CompressedRecvError::Payload(CompressionError::TooLarge { .. }))
+        );
+        crate::assert_with_log!(rejected, "bomb rejected", true, rejected);
+        crate::test_complete!("mpsc_receiver_rejects_oversized_payload");
+    }
+
+    #[test]
+    fn closed_mpsc_returns_payload_uncompressed() {
+        init_test("closed_mpsc_returns_payload_uncompressed");
+        let (tx, rx) = channel(1, compressor());
+        drop(rx);
+
+        let result = tx.try_send(large_payload());
+        let returned = matches!(
+            &result,
+            Err(mpsc::SendError::Disconnected(payload)) if *payload == large_payload()
+        );
+        crate::assert_with_log!(returned, "payload returned", true, returned);
+        let payloads = tx.compressor().metrics().payloads;
+        crate::assert_with_log!(payloads == 0, "nothing compressed", 0, payloads);
+        crate::test_complete!("closed_mpsc_returns_payload_uncompressed");
+    }
+
+    #[test]
+    fn broadcast_compresses_once_for_every_receiver() {
+        init_test("broadcast_compresses_once_for_every_receiver");
+        let cx = Cx::for_testing();
+        let (tx, mut first) = broadcast_channel(4, compressor());
+        let mut second = tx.subscribe();
+
+        let receivers = tx.send(&cx, large_payload()).expect("send");
+        crate::assert_with_log!(receivers == 2, "receivers", 2, receivers);
+
+        let a = block_on(first.recv(&cx)).expect("first recv");
+        let b = second.try_recv().expect("second recv");
+        crate::assert_with_log!(a == large_payload(), "first payload", true, a == b);
+        crate::assert_with_log!(b == large_payload(), "second payload", true, a == b);
+
+        let payloads = tx.compressor().metrics().payloads;
+        crate::assert_with_log!(payloads == 1, "compressed once", 1, payloads);
+        crate::test_complete!("broadcast_compresses_once_for_every_receiver");
+    }
+}
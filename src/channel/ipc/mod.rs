@@ -0,0 +1,1450 @@
+//! Typed, cancel-safe interprocess channel over shared memory.
+//!
+//! [`sender`] and [`receiver`] turn the two ends of a connected
+//! [`UnixStream`] into a same-host channel whose frames travel through a
+//! shared-memory ring instead of the kernel socket path. The sending side
+//! creates the ring (a size-sealed `memfd` on Linux, an unlinked `shm_open`
+//! object on other Unix targets) and passes its descriptor over the socket
+//! with `SCM_RIGHTS`. After that handshake the socket carries only one-byte
+//! wakeups, sent when the other side has parked, and doubles as the liveness
+//! channel: when a peer process exits, the kernel closes its end and the
+//! waiting side observes EOF as [`SendError::Disconnected`] or
+//! [`RecvError::Disconnected`] instead of waiting forever.
+//!
+//! # Two-Phase API
+//!
+//! The surface mirrors [`mpsc`](super::mpsc):
+//!
+//! | Step | `mpsc` | `ipc` |
+//! |------|--------|-------|
+//! | Reserve | `Sender::reserve(&cx).await` | `Sender::reserve(&cx).await` |
+//! | Commit | `SendPermit::send(value)` | `SendPermit::send(value)` |
+//! | One-call send | `Sender::send(&cx, value).await` | `Sender::send(&cx, value).await` |
+//! | Receive | `Receiver::recv(&cx).await` | `Receiver::recv(&cx).await` |
+//! | Zero-copy receive | n/a | `Receiver::recv_ref(&cx).await`, then `RecvRef::release()` |
+//!
+//! Messages implement [`IpcMessage`]. `recv` copies each frame out of the
+//! mapping into the receiver's buffer and decodes it from there. `recv_ref`
+//! copies nothing: the returned [`RecvRef`] lends a [`SharedBytes`] view
+//! that reads the frame's slot in place, and the slot (with the receive
+//! cursor behind it) stays pinned until [`RecvRef::release`]. The peer can
+//! write the mapping at any time, so the view reads through volatile
+//! accesses instead of lending a `&[u8]`. On the sending side, `encode`
+//! writes into a reusable scratch buffer that is then copied into the slot,
+//! for the same reason.
+//!
+//! # Backpressure
+//!
+//! Capacity is the ring's slot count ([`IpcConfig::slots`]). A reservation
+//! succeeds while published-but-unreleased frames plus outstanding permits
+//! leave a free slot; otherwise `reserve` queues in FIFO order until the
+//! receiver releases a frame. A stalled consumer therefore stalls producers
+//! without growing memory on either side.
+//!
+//! # Cancellation
+//!
+//! - A `reserve` future cancelled or dropped while queued gives up its place
+//!   and hands the wakeup duty to the next queued reserver.
+//! - A permit dropped or aborted without `send` returns its slot credit.
+//!   Reservations live in the sending process only, so a sender that dies
+//!   mid-reservation never strands a slot in the shared ring.
+//! - A `recv` or `recv_ref` future cancelled before completing consumes
+//!   nothing.
+//! - A [`RecvRef`] dropped without [`RecvRef::release`] still hands its slot
+//!   back, but it is a lease obligation: the armed token then reports the
+//!   leak with `ASUP-E101`.
+//!
+//! # Untrusted Peers
+//!
+//! The ring cursors live in shared memory, so each side checks the cursor
+//! the other side wrote before using it. A receiver that finds the tail
+//! moved backwards or past the ring capacity reports
+//! [`RecvError::Protocol`]; a sender that finds the head past the tail or
+//! moved backwards treats the receiver as disconnected.
+//!
+//! # Peer Death
+//!
+//! A dead receiver is observed by a waiting `reserve` (EOF on the socket) or
+//! by the next commit whose wakeup write fails. A dead sender is observed by
+//! a waiting `recv` once every frame it published has been drained.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::channel::ipc::{self, IpcConfig};
+//!
+//! // Producer process.
+//! let stream = UnixStream::connect("/run/symbols.sock").await?;
+//! let tx = ipc::sender::<Vec<u8>>(stream, IpcConfig::default()).await?;
+//! let permit = tx.reserve(&cx).await?;
+//! permit.send(symbol_bytes);
+//!
+//! // Consumer process.
+//! let (stream, _) = listener.accept().await?;
+//! let mut rx = ipc::receiver::<Vec<u8>>(stream).await?;
+//! let frame = rx.recv_ref(&cx).await?;
+//! process(frame.bytes());
+//! frame.release();
+//! ```
+
+mod ring;
+mod shm;
+
+pub use self::shm::SharedBytes;
+
+use self::ring::{CorruptFrame, Flag, ProtocolViolation, Ring};
+use crate::cx::Cx;
+use crate::net::unix::UnixStream;
+use crate::obligation::graded::{LeaseKind, ObligationToken};
+use crate::types::RegionId;
+use crate::types::outcome::Outcome;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+
+/// Payload of the handshake message that carries the ring descriptor.
+const HANDSHAKE: &[u8; 8] = b"ASUPIPC1";
+
+/// Wakeup byte written when the other side has parked.
+const WAKEUP: &[u8] = &[1];
+
+/// Shape of the shared-memory ring created by [`sender`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpcConfig {
+    /// Number of frame slots; bounds unreleased frames plus outstanding permits.
+    pub slots: usize,
+    /// Largest encoded message, in bytes, a single frame can carry.
+    pub slot_size: usize,
+}
+
+impl Default for IpcConfig {
+    fn default() -> Self {
+        Self {
+            slots: 256,
+            slot_size: 64 * 1024,
+        }
+    }
+}
+
+impl IpcConfig {
+    /// Sets the number of frame slots.
+    #[must_use]
+    pub const fn with_slots(mut self, slots: usize) -> Self {
+        self.slots = slots;
+        self
+    }
+
+    /// Sets the largest encoded message a single frame can carry.
+    #[must_use]
+    pub const fn with_slot_size(mut self, slot_size: usize) -> Self {
+        self.slot_size = slot_size;
+        self
+    }
+}
+
+/// Error returned when sending fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// The receiver was dropped or its process exited.
+    Disconnected(T),
+    /// The operation was cancelled.
+    Cancelled(T),
+    /// The ring is full (for try_reserve).
+    Full(T),
+    /// The encoded message does not fit in one frame slot.
+    TooLarge(T),
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected(_) => write!(f, "sending on a closed ipc channel"),
+            Self::Cancelled(_) => write!(f, "send operation cancelled"),
+            Self::Full(_) => write!(f, "ipc channel is full"),
+            Self::TooLarge(_) => write!(f, "message does not fit in an ipc frame"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for SendError<T> {}
+
+/// Error returned when receiving fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecvError {
+    /// Every sender was dropped or the sending process exited, and the ring
+    /// is drained.
+    Disconnected,
+    /// The receive operation was cancelled.
+    Cancelled,
+    /// The ring is empty (for try_recv).
+    Empty,
+    /// The next frame could not be decoded; it has been consumed.
+    Decode(DecodeError),
+    /// The sender left the ring cursors in a state the protocol cannot
+    /// reach; no further frame can be trusted.
+    Protocol(&'static str),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => write!(f, "receiving on a closed ipc channel"),
+            Self::Cancelled => write!(f, "[ASUP-E203] receive operation cancelled"),
+            Self::Empty => write!(f, "ipc channel is empty"),
+            Self::Decode(err) => write!(f, "malformed ipc frame: {err}"),
+            Self::Protocol(reason) => write!(f, "ipc ring protocol violation: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl From<CorruptFrame> for RecvError {
+    fn from(_: CorruptFrame) -> Self {
+        Self::Decode(DecodeError::new("frame length exceeds the slot size"))
+    }
+}
+
+impl From<ProtocolViolation> for RecvError {
+    fn from(violation: ProtocolViolation) -> Self {
+        Self::Protocol(violation.0)
+    }
+}
+
+/// Error returned by [`IpcMessage::decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeError {
+    reason: &'static str,
+}
+
+impl DecodeError {
+    /// Creates a decode error with a static reason.
+    #[must_use]
+    pub const fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+
+    /// Returns the reason the frame was rejected.
+    #[must_use]
+    pub const fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A message that can cross an IPC channel as a single frame.
+pub trait IpcMessage: Sized {
+    /// Encodes `self` into `frame`, returning the number of bytes written, or
+    /// `None` when the encoding does not fit.
+    fn encode(&self, frame: &mut [u8]) -> Option<usize>;
+
+    /// Decodes a message from a received frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] when `frame` is not a valid encoding.
+    fn decode(frame: &[u8]) -> Result<Self, DecodeError>;
+}
+
+impl IpcMessage for Vec<u8> {
+    fn encode(&self, frame: &mut [u8]) -> Option<usize> {
+        frame.get_mut(..self.len())?.copy_from_slice(self);
+        Some(self.len())
+    }
+
+    fn decode(frame: &[u8]) -> Result<Self, DecodeError> {
+        Ok(frame.to_vec())
+    }
+}
+
+impl IpcMessage for String {
+    fn encode(&self, frame: &mut [u8]) -> Option<usize> {
+        frame
+            .get_mut(..self.len())?
+            .copy_from_slice(self.as_bytes());
+        Some(self.len())
+    }
+
+    fn decode(frame: &[u8]) -> Result<Self, DecodeError> {
+        std::str::from_utf8(frame)
+            .map(str::to_owned)
+            .map_err(|_| DecodeError::new("frame is not valid UTF-8"))
+    }
+}
+
+/// Creates a shared-memory ring and hands it to the peer at the other end of
+/// `stream`, returning the sending half.
+///
+/// The peer completes the connection with [`receiver`]. The handshake is a
+/// single message, so both halves can be created from one task.
+///
+/// # Errors
+///
+/// Returns an error if the ring cannot be created or mapped, or if the
+/// descriptor cannot be passed over `stream`.
+#[allow(clippy::future_not_send)]
+pub async fn sender<T>(stream: UnixStream, config: IpcConfig) -> io::Result<Sender<T>> {
+    let ring = Ring::create(config.slots, config.slot_size)?;
+    let sent = stream.send_with_fd(HANDSHAKE, &[ring.fd()]).await?;
+    if sent != HANDSHAKE.len() {
+        return Err(io::Error::new(io::ErrorKind::WriteZero, "short ipc handshake write"));
+    }
+    Ok(Sender::from_parts(ring, stream))
+}
+
+/// Accepts the ring offered by [`sender`] at the other end of `stream`,
+/// returning the receiving half.
+///
+/// # Errors
+///
+/// Returns an error if the peer closes before the handshake, sends anything
+/// other than a ring descriptor, or the ring fails validation.
+#[allow(clippy::future_not_send)]
+pub async fn receiver<T>(stream: UnixStream) -> io::Result<Receiver<T>> {
+    let mut hello = [0u8; HANDSHAKE.len()];
+    let (read, fds) = stream.recv_with_fd(&mut hello, 1).await?;
+    if read == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "peer closed before the ipc handshake",
+        ));
+    }
+    if hello[..read] != HANDSHAKE[..] {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected ipc handshake payload",
+        ));
+    }
+    let fd = fds.into_iter().next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "ipc handshake carried no descriptor",
+        )
+    })?;
+    Ok(Receiver::from_parts(Ring::open(fd)?, stream))
+}
+
+/// Sends one wakeup byte; a full socket buffer already holds a pending one.
+fn wake_peer(stream: &UnixStream) -> io::Result<()> {
+    match stream.try_write_nosignal(WAKEUP) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Drains wakeup bytes written by the peer.
+///
+/// Returns `Ready(true)` once at least one wakeup was consumed,
+/// `Ready(false)` when the peer closed its end or the socket failed, and
+/// `Pending` with the task registered for readability otherwise.
+fn poll_peer(stream: &UnixStream, task: &Context<'_>) -> Poll<bool> {
+    let mut buf = [0u8; 64];
+    let mut woke = false;
+    loop {
+        match stream.poll_read_shared(task, &mut buf) {
+            Poll::Ready(Ok(0) | Err(_)) => return Poll::Ready(false),
+            Poll::Ready(Ok(_)) => woke = true,
+            Poll::Pending if woke => return Poll::Ready(true),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+/// Hands every slot below `head` back to the sender, waking it if it parked.
+fn release_frames(ring: &Ring, stream: &UnixStream, head: u64) {
+    ring.release(head);
+    if ring.flag(Flag::SenderParked).swap(0, Ordering::SeqCst) != 0 {
+        // A failed wakeup means the sender is gone, which it can no longer
+        // observe anyway; the next receive sees the closed socket.
+        let _ = wake_peer(stream);
+    }
+}
+
+#[inline]
+fn reserve_ref_obligation(region: RegionId) -> Option<ObligationToken<LeaseKind>> {
+    if region.as_u64() == 0 {
+        None
+    } else {
+        Some(ObligationToken::reserve("ipc-recv-ref", region))
+    }
+}
+
+#[derive(Debug)]
+struct SenderShared {
+    ring: Ring,
+    stream: UnixStream,
+    state: Mutex<SenderState>,
+    /// Serializes frame encoding and tail publication; guards the scratch
+    /// buffer frames are encoded into before being copied to their slot.
+    commit: Mutex<Vec<u8>>,
+    senders: AtomicUsize,
+    disconnected: AtomicBool,
+}
+
+#[derive(Debug, Default)]
+struct SenderState {
+    /// Granted permits not yet committed or aborted.
+    reserved: u64,
+    /// Reserve futures waiting for capacity, in FIFO order. Only the front
+    /// waiter watches the socket for the receiver's wakeup.
+    waiters: VecDeque<(u64, Waker)>,
+    next_waiter: u64,
+}
+
+impl SenderShared {
+    fn has_capacity(&self, state: &SenderState) -> bool {
+        self.ring.in_flight().saturating_add(state.reserved) < self.ring.slots()
+    }
+
+    fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Acquire)
+            || self.ring.flag(Flag::ReceiverClosed).load(Ordering::SeqCst) != 0
+    }
+
+    /// Records that the receiver is gone and wakes every queued reserver.
+    fn disconnect(&self) {
+        self.disconnected.store(true, Ordering::Release);
+        let wakers: Vec<Waker> = {
+            let state = self.state.lock();
+            state
+                .waiters
+                .iter()
+                .map(|(_, waker)| waker.clone())
+                .collect()
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    fn wake_front(&self) {
+        let waker = {
+            let state = self.state.lock();
+            state.waiters.front().map(|(_, waker)| waker.clone())
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Returns one permit's slot credit and lets the front reserver retry.
+    fn release_permit(&self) {
+        {
+            let mut state = self.state.lock();
+            debug_assert!(
+                state.reserved > 0,
+                "ipc permit released without reservation"
+            );
+            state.reserved = state.reserved.saturating_sub(1);
+        }
+        self.wake_front();
+    }
+
+    /// Wakes the receiver if it parked waiting for a frame.
+    fn wake_receiver(&self) {
+        let parked = self.ring.flag(Flag::ReceiverParked);
+        if parked.swap(0, Ordering::SeqCst) != 0 && wake_peer(&self.stream).is_err() {
+            self.disconnect();
+        }
+    }
+}
+
+/// The sending half of an IPC channel.
+///
+/// Clones share one ring and one socket; frames from different clones are
+/// published in commit order.
+pub struct Sender<T> {
+    shared: Arc<SenderShared>,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Sender<T> {
+    fn from_parts(ring: Ring, stream: UnixStream) -> Self {
+        Self {
+            shared: Arc::new(SenderShared {
+                ring,
+                stream,
+                state: Mutex::new(SenderState::default()),
+                commit: Mutex::new(Vec::new()),
+                senders: AtomicUsize::new(1),
+                disconnected: AtomicBool::new(false),
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reserves a frame slot for sending.
+    #[must_use]
+    pub fn reserve<'a>(&'a self, cx: &'a Cx) -> Reserve<'a, T> {
+        Reserve {
+            sender: self,
+            cx,
+            waiter: None,
+        }
+    }
+
+    /// Attempts to reserve a frame slot without waiting.
+    ///
+    /// Returns `Full` while other reservers are queued, to preserve FIFO
+    /// ordering.
+    pub fn try_reserve(&self) -> Result<SendPermit<'_, T>, SendError<()>> {
+        if self.shared.is_disconnected() {
+            return Err(SendError::Disconnected(()));
+        }
+        let mut state = self.shared.state.lock();
+        if !state.waiters.is_empty() || !self.shared.has_capacity(&state) {
+            return Err(SendError::Full(()));
+        }
+        state.reserved += 1;
+        drop(state);
+        Ok(SendPermit {
+            sender: self,
+            done: false,
+        })
+    }
+
+    /// Returns the number of frame slots in the ring.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.shared.ring.slots() as usize
+    }
+
+    /// Returns the largest encoded message a single frame can carry.
+    #[must_use]
+    pub fn max_frame_len(&self) -> usize {
+        self.shared.ring.slot_size()
+    }
+
+    /// Returns the number of published frames the receiver has not released.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.shared.ring.in_flight() as usize
+    }
+
+    /// Returns `true` once the receiver is known to be gone.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_disconnected()
+    }
+}
+
+impl<T: IpcMessage> Sender<T> {
+    /// Convenience method: reserve and send in one step.
+    pub async fn send(&self, cx: &Cx, value: T) -> Result<(), SendError<T>> {
+        match self.reserve(cx).await {
+            Ok(permit) => permit.try_send(value),
+            Err(SendError::Disconnected(())) => Err(SendError::Disconnected(value)),
+            Err(SendError::Cancelled(())) => Err(SendError::Cancelled(value)),
+            Err(SendError::Full(())) => Err(SendError::Full(value)),
+            Err(SendError::TooLarge(())) => Err(SendError::TooLarge(value)),
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: Arc::clone(&self.shared),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared
+                .ring
+                .flag(Flag::SenderClosed)
+                .store(1, Ordering::SeqCst);
+            self.shared.wake_receiver();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("capacity", &self.capacity())
+            .field("in_flight", &self.in_flight())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// Future returned by [`Sender::reserve`].
+pub struct Reserve<'a, T> {
+    sender: &'a Sender<T>,
+    cx: &'a Cx,
+    waiter: Option<u64>,
+}
+
+impl<T> Reserve<'_, T> {
+    /// Leaves the waiter queue, handing the socket watch to the next waiter.
+    fn leave_queue(&mut self) {
+        let Some(id) = self.waiter.take() else {
+            return;
+        };
+        let next = {
+            let mut state = self.sender.shared.state.lock();
+            let was_front = state.waiters.front().is_some_and(|(front, _)| *front == id);
+            state.waiters.retain(|(waiter, _)| *waiter != id);
+            if was_front {
+                state.waiters.front().map(|(_, waker)| waker.clone())
+            } else {
+                None
+            }
+        };
+        if let Some(waker) = next {
+            waker.wake();
+        }
+    }
+}
+
+impl<'a, T> Future for Reserve<'a, T> {
+    type Output = Result<SendPermit<'a, T>, SendError<()>>;
+
+    fn poll(self: Pin<&mut Self>, task: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let sender = this.sender;
+        let shared = &sender.shared;
+
+        loop {
+            if this.cx.checkpoint().is_err() {
+                this.cx.trace("ipc::reserve cancelled");
+                this.leave_queue();
+                return Poll::Ready(Err(SendError::Cancelled(())));
+            }
+            if shared.is_disconnected() {
+                this.leave_queue();
+                return Poll::Ready(Err(SendError::Disconnected(())));
+            }
+
+            let mut state = shared.state.lock();
+            let front = state.waiters.front().map(|(id, _)| *id);
+            if (front.is_none() || front == this.waiter) && shared.has_capacity(&state) {
+                state.reserved += 1;
+                if this.waiter.take().is_some() {
+                    state.waiters.pop_front();
+                }
+                // The next waiter becomes the one watching the socket.
+                let next = state.waiters.front().map(|(_, waker)| waker.clone());
+                drop(state);
+                if let Some(waker) = next {
+                    waker.wake();
+                }
+                return Poll::Ready(Ok(SendPermit {
+                    sender,
+                    done: false,
+                }));
+            }
+
+            if let Some(id) = this.waiter {
+                if let Some((_, waker)) = state.waiters.iter_mut().find(|(w, _)| *w == id)
+                    && !waker.will_wake(task.waker())
+                {
+                    waker.clone_from(task.waker());
+                }
+            } else {
+                let id = state.next_waiter;
+                state.next_waiter += 1;
+                state.waiters.push_back((id, task.waker().clone()));
+                this.waiter = Some(id);
+            }
+            let is_front = state.waiters.front().map(|(id, _)| *id) == this.waiter;
+            drop(state);
+            if !is_front {
+                return Poll::Pending;
+            }
+
+            // Ask the receiver for a wakeup, then recheck before parking so a
+            // release racing with the flag is never missed.
+            shared
+                .ring
+                .flag(Flag::SenderParked)
+                .store(1, Ordering::SeqCst);
+            if shared.has_capacity(&shared.state.lock()) {
+                continue;
+            }
+            match poll_peer(&shared.stream, task) {
+                Poll::Ready(true) => {}
+                Poll::Ready(false) => shared.disconnect(),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Drop for Reserve<'_, T> {
+    fn drop(&mut self) {
+        self.leave_queue();
+    }
+}
+
+/// A permit to send a single frame.
+#[must_use = "SendPermit must be consumed via send() or abort()"]
+pub struct SendPermit<'a, T> {
+    sender: &'a Sender<T>,
+    done: bool,
+}
+
+impl<T: IpcMessage> SendPermit<'_, T> {
+    /// Commits the reserved slot, encoding the value into it.
+    ///
+    /// Returns `Err(SendError::Disconnected(value))` if the receiver is gone
+    /// and `Err(SendError::TooLarge(value))` if the encoding does not fit in a
+    /// frame; the slot credit is returned in both cases.
+    pub fn send(self, value: T) -> Outcome<(), SendError<T>> {
+        match self.try_send(value) {
+            Ok(()) => Outcome::Ok(()),
+            Err(error) => Outcome::Err(error),
+        }
+    }
+
+    /// Commits the reserved slot, returning an error if it cannot be used.
+    pub fn try_send(mut self, value: T) -> Result<(), SendError<T>> {
+        self.done = true;
+        let shared = &self.sender.shared;
+        let mut commit = shared.commit.lock();
+        if shared.is_disconnected() {
+            drop(commit);
+            shared.release_permit();
+            return Err(SendError::Disconnected(value));
+        }
+        // Holding a permit keeps `tail - head < slots`, so slot `tail` is free
+        // unless the receiver corrupted the head.
+        let Ok(seq) = shared.ring.next_free() else {
+            drop(commit);
+            shared.release_permit();
+            shared.disconnect();
+            return Err(SendError::Disconnected(value));
+        };
+        let encoded = shared
+            .ring
+            .write_frame(seq, &mut commit, |frame| value.encode(frame));
+        if encoded.is_none() {
+            drop(commit);
+            shared.release_permit();
+            return Err(SendError::TooLarge(value));
+        }
+        shared.ring.publish(seq + 1);
+        drop(commit);
+        // The published frame now holds the slot. Until the credit is dropped
+        // a racing reserver counts the slot twice, so wake the front waiter
+        // to recheck once it is.
+        shared.release_permit();
+        shared.wake_receiver();
+        Ok(())
+    }
+}
+
+impl<T> SendPermit<'_, T> {
+    /// Aborts the reserved slot without sending.
+    pub fn abort(mut self) {
+        self.done = true;
+        self.sender.shared.release_permit();
+    }
+}
+
+impl<T> Drop for SendPermit<'_, T> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.sender.shared.release_permit();
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendPermit<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendPermit")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of an IPC channel.
+pub struct Receiver<T> {
+    ring: Ring,
+    stream: UnixStream,
+    /// Next frame to hand out; equals the shared head between receives.
+    next: u64,
+    /// Frame payload copied out of the mapping for decoding.
+    buffer: Vec<u8>,
+    disconnected: bool,
+    unreleased_refs: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Receiver<T> {
+    fn from_parts(ring: Ring, stream: UnixStream) -> Self {
+        let next = ring.head();
+        Self {
+            ring,
+            stream,
+            next,
+            buffer: Vec::new(),
+            disconnected: false,
+            unreleased_refs: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a future resolving to a zero-copy reference to the next frame.
+    #[must_use]
+    pub fn recv_ref<'a, Caps>(&'a mut self, cx: &'a Cx<Caps>) -> RecvRefFuture<'a, T, Caps> {
+        RecvRefFuture {
+            receiver: Some(self),
+            cx,
+        }
+    }
+
+    /// Attempts to take a zero-copy reference to the next frame without
+    /// waiting.
+    ///
+    /// The reference's obligation is scoped to the current task's region when
+    /// one is installed.
+    pub fn try_recv_ref(&mut self) -> Result<RecvRef<'_, T>, RecvError> {
+        self.check_frame()?;
+        self.take_ref(Cx::current().map(|cx| cx.region_id()))
+    }
+
+    /// Returns the number of published frames not yet received.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ring.tail().saturating_sub(self.next) as usize
+    }
+
+    /// Returns `true` if no published frame is waiting.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of frame slots in the ring.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.ring.slots() as usize
+    }
+
+    /// Returns `true` once every sender is gone.
+    ///
+    /// Frames published before the close can still be received.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.disconnected || self.ring.flag(Flag::SenderClosed).load(Ordering::SeqCst) != 0
+    }
+
+    /// Returns how many [`RecvRef`]s were dropped without being released.
+    #[must_use]
+    pub fn unreleased_refs(&self) -> u64 {
+        self.unreleased_refs
+    }
+
+    /// Returns `Ok` when a frame is ready, `Empty` or `Disconnected` otherwise.
+    fn check_frame(&self) -> Result<(), RecvError> {
+        if self.ring.published(self.next)? > 0 {
+            return Ok(());
+        }
+        if self.is_closed() {
+            // Re-read the tail: the final frames may have been published
+            // between the first check and the close.
+            return if self.ring.published(self.next)? > 0 {
+                Ok(())
+            } else {
+                Err(RecvError::Disconnected)
+            };
+        }
+        Err(RecvError::Empty)
+    }
+
+    fn poll_frame<Caps>(
+        &mut self,
+        cx: &Cx<Caps>,
+        task: &Context<'_>,
+    ) -> Poll<Result<(), RecvError>> {
+        loop {
+            if cx.checkpoint().is_err() {
+                cx.trace("ipc::recv cancelled");
+                return Poll::Ready(Err(RecvError::Cancelled));
+            }
+            match self.check_frame() {
+                Err(RecvError::Empty) => {}
+                ready => return Poll::Ready(ready),
+            }
+
+            // Ask the sender for a wakeup, then recheck before parking so a
+            // publish racing with the flag is never missed.
+            self.ring
+                .flag(Flag::ReceiverParked)
+                .store(1, Ordering::SeqCst);
+            if self.ring.tail() > self.next {
+                continue;
+            }
+            match poll_peer(&self.stream, task) {
+                Poll::Ready(true) => {}
+                Poll::Ready(false) => self.disconnected = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn take_ref(&mut self, region: Option<RegionId>) -> Result<RecvRef<'_, T>, RecvError> {
+        let Self {
+            ring,
+            stream,
+            next,
+            unreleased_refs,
+            ..
+        } = self;
+        let (ring, stream): (&Ring, &UnixStream) = (ring, stream);
+        let seq = *next;
+        match ring.frame(seq) {
+            Ok(frame) => Ok(RecvRef {
+                frame,
+                ring,
+                stream,
+                next,
+                unreleased_refs,
+                obligation: region.and_then(reserve_ref_obligation),
+                released: false,
+                _marker: PhantomData,
+            }),
+            Err(corrupt) => {
+                *next = seq + 1;
+                release_frames(ring, stream, *next);
+                Err(corrupt.into())
+            }
+        }
+    }
+}
+
+impl<T: IpcMessage> Receiver<T> {
+    /// Creates a future that receives and decodes the next frame.
+    ///
+    /// The frame is released as soon as it is decoded. A frame that fails to
+    /// decode is consumed and reported as [`RecvError::Decode`].
+    #[must_use]
+    pub fn recv<'a, Caps>(&'a mut self, cx: &'a Cx<Caps>) -> Recv<'a, T, Caps> {
+        Recv {
+            receiver: self,
+            cx,
+        }
+    }
+
+    /// Attempts to receive and decode the next frame without waiting.
+    pub fn try_recv(&mut self) -> Result<T, RecvError> {
+        self.check_frame()?;
+        self.take_value()
+    }
+
+    fn take_value(&mut self) -> Result<T, RecvError> {
+        let seq = self.next;
+        let value = match self.ring.read_frame(seq, &mut self.buffer) {
+            Ok(()) => T::decode(&self.buffer).map_err(RecvError::Decode),
+            Err(corrupt) => Err(corrupt.into()),
+        };
+        self.next = seq + 1;
+        release_frames(&self.ring, &self.stream, self.next);
+        value
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.ring
+            .flag(Flag::ReceiverClosed)
+            .store(1, Ordering::SeqCst);
+        if self.ring.flag(Flag::SenderParked).swap(0, Ordering::SeqCst) != 0 {
+            let _ = wake_peer(&self.stream);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("closed", &self.is_closed())
+            .field("unreleased_refs", &self.unreleased_refs)
+            .finish()
+    }
+}
+
+/// Future returned by [`Receiver::recv`].
+pub struct Recv<'a, T, Caps = crate::cx::cap::All> {
+    receiver: &'a mut Receiver<T>,
+    cx: &'a Cx<Caps>,
+}
+
+impl<T: IpcMessage, Caps> Future for Recv<'_, T, Caps> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, task: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.receiver.poll_frame(this.cx, task) {
+            Poll::Ready(Ok(())) => Poll::Ready(this.receiver.take_value()),
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Future returned by [`Receiver::recv_ref`].
+pub struct RecvRefFuture<'a, T, Caps = crate::cx::cap::All> {
+    receiver: Option<&'a mut Receiver<T>>,
+    cx: &'a Cx<Caps>,
+}
+
+impl<'a, T, Caps> Future for RecvRefFuture<'a, T, Caps> {
+    type Output = Result<RecvRef<'a, T>, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, task: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let receiver = this
+            .receiver
+            .as_deref_mut()
+            .expect("RecvRefFuture polled after completion");
+        match receiver.poll_frame(this.cx, task) {
+            Poll::Ready(Ok(())) => {
+                let receiver = this.receiver.take().expect("receiver present");
+                Poll::Ready(receiver.take_ref(Some(this.cx.region_id())))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A zero-copy reference to a received frame.
+///
+/// [`bytes`](Self::bytes) views the payload in place inside the shared
+/// mapping. The frame's slot, and the receive cursor behind it, stay with
+/// the receiver and out of the sender's capacity until
+/// [`release`](Self::release) hands them back, so a sender that follows the
+/// ring protocol never overwrites a viewed frame. The reference
+/// holds a lease obligation scoped to the receiving task's region: dropping
+/// it unreleased still returns the slot so the ring cannot wedge, but the
+/// armed obligation then panics with `ASUP-E101`, the same as any other
+/// leaked lease. In the root region, where no obligation can be held, the
+/// leak is only counted by [`Receiver::unreleased_refs`].
+#[must_use = "RecvRef must be released with release()"]
+pub struct RecvRef<'a, T> {
+    frame: SharedBytes<'a>,
+    ring: &'a Ring,
+    stream: &'a UnixStream,
+    next: &'a mut u64,
+    unreleased_refs: &'a mut u64,
+    obligation: Option<ObligationToken<LeaseKind>>,
+    released: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RecvRef<'_, T> {
+    /// Returns an in-place view of the frame payload.
+    ///
+    /// The view borrows this reference, so it cannot outlive the
+    /// [`release`](Self::release) that hands the slot back.
+    #[must_use]
+    pub fn bytes(&self) -> SharedBytes<'_> {
+        self.frame
+    }
+
+    /// Returns the payload length in bytes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// Returns `true` if the payload is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frame.is_empty()
+    }
+
+    /// Releases the frame's slot back to the sender and commits the lease.
+    pub fn release(mut self) {
+        self.release_slot();
+        if let Some(obligation) = self.obligation.take() {
+            let _proof = obligation.commit();
+        }
+    }
+
+    fn release_slot(&mut self) {
+        self.released = true;
+        *self.next += 1;
+        release_frames(self.ring, self.stream, *self.next);
+    }
+}
+
+impl<T: IpcMessage> RecvRef<'_, T> {
+    /// Decodes the frame without releasing it.
+    ///
+    /// [`IpcMessage::decode`] takes a byte slice, so this copies the payload
+    /// out of the mapping first; read [`bytes`](Self::bytes) to stay
+    /// zero-copy.
+    pub fn decode(&self) -> Result<T, DecodeError> {
+        T::decode(&self.frame.to_vec())
+    }
+}
+
+impl<T> Drop for RecvRef<'_, T> {
+    fn drop(&mut self) {
+        if !self.released {
+            self.release_slot();
+            *self.unreleased_refs += 1;
+            // The still-armed obligation drops next and reports the leak.
+        }
+    }
+}
+
+impl<T> fmt::Debug for RecvRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecvRef")
+            .field("seq", &*self.next)
+            .field("len", &self.frame.len())
+            .field("released", &self.released)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::future_not_send)]
+    use super::*;
+    use crate::types::{Budget, TaskId};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        futures_lite::future::block_on(f)
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let waker = Waker::noop();
+        let mut task = Context::from_waker(waker);
+        Pin::new(future).poll(&mut task)
+    }
+
+    fn pair<T>(config: IpcConfig) -> (Sender<T>, Receiver<T>) {
+        let (a, b) = UnixStream::pair().expect("socketpair");
+        let tx = block_on(sender::<T>(a, config)).expect("sender handshake");
+        let rx = block_on(receiver::<T>(b)).expect("receiver handshake");
+        (tx, rx)
+    }
+
+    #[test]
+    fn frames_round_trip_in_order() {
+        init_test("frames_round_trip_in_order");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(4));
+
+        for round in 0..20_u8 {
+            let payload = vec![round; usize::from(round) * 7];
+            block_on(tx.send(&cx, payload.clone())).expect("send");
+            let got = block_on(rx.recv(&cx)).expect("recv");
+            crate::assert_with_log!(got == payload, "payload", payload, got);
+        }
+        crate::assert_with_log!(tx.in_flight() == 0, "drained", 0, tx.in_flight());
+        crate::test_complete!("frames_round_trip_in_order");
+    }
+
+    #[test]
+    fn full_ring_applies_backpressure_until_release() {
+        init_test("full_ring_applies_backpressure_until_release");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<String>(IpcConfig::default().with_slots(2));
+
+        block_on(tx.send(&cx, "a".to_owned())).expect("send a");
+        let held = tx.try_reserve().expect("second slot");
+        let full = tx.try_reserve();
+        crate::assert_with_log!(
+            matches!(full, Err(SendError::Full(()))),
+            "reserved slot counts toward capacity",
+            "Full",
+            full
+        );
+
+        let mut waiting = tx.reserve(&cx);
+        let pending = poll_once(&mut waiting).is_pending();
+        crate::assert_with_log!(pending, "reserve waits", true, pending);
+
+        let got = block_on(rx.recv(&cx)).expect("recv a");
+        crate::assert_with_log!(got == "a", "first frame", "a", got);
+        let granted = poll_once(&mut waiting);
+        crate::assert_with_log!(
+            matches!(granted, Poll::Ready(Ok(_))),
+            "release frees a slot",
+            "Ready(Ok)",
+            granted
+        );
+        drop(granted);
+        drop(waiting);
+        let _ = held.send("b".to_owned());
+        let got = block_on(rx.recv(&cx)).expect("recv b");
+        crate::assert_with_log!(got == "b", "second frame", "b", got);
+        crate::test_complete!("full_ring_applies_backpressure_until_release");
+    }
+
+    #[test]
+    fn cancelled_reservations_return_their_slots() {
+        init_test("cancelled_reservations_return_their_slots");
+        let cx = Cx::for_testing();
+        let (tx, _rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1));
+
+        let permit = tx.try_reserve().expect("reserve");
+        drop(permit);
+        let permit = tx.try_reserve().expect("dropped permit returned its slot");
+        permit.abort();
+
+        let held = tx.try_reserve().expect("aborted permit returned its slot");
+        let mut waiting = tx.reserve(&cx);
+        let pending = poll_once(&mut waiting).is_pending();
+        crate::assert_with_log!(pending, "reserve queued", true, pending);
+        cx.set_cancel_requested(true);
+        let cancelled = poll_once(&mut waiting);
+        crate::assert_with_log!(
+            matches!(cancelled, Poll::Ready(Err(SendError::Cancelled(())))),
+            "queued reserve observes cancellation",
+            "Cancelled",
+            cancelled
+        );
+        drop(waiting);
+        cx.set_cancel_requested(false);
+        drop(held);
+        let reserved = tx.try_reserve().is_ok();
+        crate::assert_with_log!(reserved, "queue and credit drained", true, reserved);
+        crate::test_complete!("cancelled_reservations_return_their_slots");
+    }
+
+    #[test]
+    fn oversized_message_is_rejected_without_consuming_a_slot() {
+        init_test("oversized_message_is_rejected_without_consuming_a_slot");
+        let (tx, _rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1).with_slot_size(8));
+
+        let permit = tx.try_reserve().expect("reserve");
+        let result = permit.try_send(vec![0; 9]);
+        crate::assert_with_log!(
+            matches!(result, Err(SendError::TooLarge(_))),
+            "too large",
+            "TooLarge",
+            result
+        );
+        let reserved = tx.try_reserve().is_ok();
+        crate::assert_with_log!(reserved, "slot credit returned", true, reserved);
+        crate::test_complete!("oversized_message_is_rejected_without_consuming_a_slot");
+    }
+
+    #[test]
+    fn recv_ref_borrows_the_mapping_until_released() {
+        init_test("recv_ref_borrows_the_mapping_until_released");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1));
+
+        block_on(tx.send(&cx, b"zero-copy".to_vec())).expect("send");
+        let frame = block_on(rx.recv_ref(&cx)).expect("recv_ref");
+        crate::assert_with_log!(
+            frame.bytes() == &b"zero-copy"[..],
+            "payload",
+            "zero-copy",
+            frame.bytes()
+        );
+        // The view reads the slot in place: a write into the pinned slot
+        // shows through it.
+        let sender_ring = &tx.shared.ring;
+        let seq = sender_ring.head();
+        sender_ring
+            .write_frame(seq, &mut Vec::new(), |slot| {
+                slot[..9].copy_from_slice(b"rewritten");
+                Some(9)
+            })
+            .expect("rewrite slot");
+        crate::assert_with_log!(
+            frame.bytes() == &b"rewritten"[..],
+            "view is not a copy",
+            "rewritten",
+            frame.bytes()
+        );
+        let full = tx.try_reserve().is_err();
+        crate::assert_with_log!(full, "held frame keeps its slot", true, full);
+        frame.release();
+        let reserved = tx.try_reserve().is_ok();
+        crate::assert_with_log!(reserved, "release frees the slot", true, reserved);
+        crate::assert_with_log!(
+            rx.unreleased_refs() == 0,
+            "no leaks",
+            0,
+            rx.unreleased_refs()
+        );
+        crate::test_complete!("recv_ref_borrows_the_mapping_until_released");
+    }
+
+    #[test]
+    fn unreleased_recv_ref_reports_leaked_obligation() {
+        init_test("unreleased_recv_ref_reports_leaked_obligation");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1));
+
+        block_on(tx.send(&cx, vec![1, 2, 3])).expect("send");
+        let leaked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let frame = block_on(rx.recv_ref(&cx)).expect("recv_ref");
+            drop(frame);
+        }));
+        let message = leaked
+            .expect_err("dropping an unreleased ref must panic")
+            .downcast::<String>()
+            .map(|message| *message)
+            .unwrap_or_default();
+        crate::assert_with_log!(
+            message.contains("ASUP-E101"),
+            "leak reported",
+            "ASUP-E101",
+            message
+        );
+        crate::assert_with_log!(
+            rx.unreleased_refs() == 1,
+            "leak counted",
+            1,
+            rx.unreleased_refs()
+        );
+        let reserved = tx.try_reserve().is_ok();
+        crate::assert_with_log!(reserved, "slot still reclaimed", true, reserved);
+        crate::test_complete!("unreleased_recv_ref_reports_leaked_obligation");
+    }
+
+    #[test]
+    fn root_region_recv_ref_leak_is_counted() {
+        init_test("root_region_recv_ref_leak_is_counted");
+        let cx = Cx::for_testing();
+        let root_cx: Cx = Cx::new(
+            RegionId::new_for_test(0, 0),
+            TaskId::new_for_test(0, 0),
+            Budget::INFINITE,
+        );
+        let (tx, mut rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1));
+
+        block_on(tx.send(&cx, vec![7])).expect("send");
+        let frame = block_on(rx.recv_ref(&root_cx)).expect("recv_ref");
+        drop(frame);
+        crate::assert_with_log!(
+            rx.unreleased_refs() == 1,
+            "leak counted",
+            1,
+            rx.unreleased_refs()
+        );
+        crate::test_complete!("root_region_recv_ref_leak_is_counted");
+    }
+
+    #[test]
+    fn dropped_receiver_disconnects_waiting_sender() {
+        init_test("dropped_receiver_disconnects_waiting_sender");
+        let cx = Cx::for_testing();
+        let (tx, rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(1));
+
+        block_on(tx.send(&cx, vec![1])).expect("send");
+        let mut waiting = tx.reserve(&cx);
+        let pending = poll_once(&mut waiting).is_pending();
+        crate::assert_with_log!(pending, "reserve waits on full ring", true, pending);
+        drop(rx);
+        let result = block_on(&mut waiting);
+        crate::assert_with_log!(
+            matches!(result, Err(SendError::Disconnected(()))),
+            "disconnected",
+            "Disconnected",
+            result
+        );
+        crate::test_complete!("dropped_receiver_disconnects_waiting_sender");
+    }
+
+    #[test]
+    fn dropped_sender_drains_then_disconnects() {
+        init_test("dropped_sender_drains_then_disconnects");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<String>(IpcConfig::default().with_slots(4));
+
+        block_on(tx.send(&cx, "last".to_owned())).expect("send");
+        drop(tx);
+        let got = block_on(rx.recv(&cx)).expect("frame survives close");
+        crate::assert_with_log!(got == "last", "drained", "last", got);
+        let closed = block_on(rx.recv(&cx));
+        crate::assert_with_log!(
+            closed == Err(RecvError::Disconnected),
+            "disconnected",
+            RecvError::Disconnected,
+            closed
+        );
+        crate::test_complete!("dropped_sender_drains_then_disconnects");
+    }
+
+    #[test]
+    fn undecodable_frame_is_consumed() {
+        init_test("undecodable_frame_is_consumed");
+        let cx = Cx::for_testing();
+        let (a, b) = UnixStream::pair().expect("socketpair");
+        let tx = block_on(sender::<Vec<u8>>(a, IpcConfig::default()));
+        let tx = tx.expect("sender");
+        let mut rx = block_on(receiver::<String>(b)).expect("receiver");
+
+        block_on(tx.send(&cx, vec![0xff, 0xfe])).expect("send");
+        block_on(tx.send(&cx, b"ok".to_vec())).expect("send");
+        let bad = rx.try_recv();
+        crate::assert_with_log!(
+            matches!(bad, Err(RecvError::Decode(_))),
+            "decode error",
+            "Decode",
+            bad
+        );
+        let good = rx.try_recv();
+        crate::assert_with_log!(good.as_deref() == Ok("ok"), "next frame", "ok", good);
+        crate::test_complete!("undecodable_frame_is_consumed");
+    }
+
+    #[test]
+    fn corrupted_cursors_are_protocol_errors_not_panics() {
+        init_test("corrupted_cursors_are_protocol_errors_not_panics");
+        let cx = Cx::for_testing();
+        let (tx, mut rx) = pair::<Vec<u8>>(IpcConfig::default().with_slots(4));
+        block_on(tx.send(&cx, b"one".to_vec())).expect("send");
+
+        // A hostile sender pushes the tail far past the ring capacity.
+        rx.ring.publish(u64::MAX);
+        let overrun = rx.try_recv();
+        crate::assert_with_log!(
+            matches!(overrun, Err(RecvError::Protocol(_))),
+            "receiver rejects the tail",
+            "Protocol",
+            overrun
+        );
+
+        // A hostile receiver moves the head past the tail.
+        tx.shared.ring.publish(1);
+        rx.ring.release(7);
+        let sent = block_on(tx.send(&cx, b"two".to_vec()));
+        crate::assert_with_log!(
+            matches!(sent, Err(SendError::Disconnected(_))),
+            "sender treats the receiver as gone",
+            "Disconnected",
+            sent
+        );
+        crate::test_complete!("corrupted_cursors_are_protocol_errors_not_panics");
+    }
+}
@@ -0,0 +1,390 @@
+//! Single-producer, single-consumer frame ring laid out in shared memory.
+//!
+//! The mapping starts with a fixed header followed by `slots` equally sized
+//! frame slots:
+//!
+//! | Offset | Field | Written by |
+//! |--------|-------|------------|
+//! | 0 | magic (`ASUPIPC1`) | creator |
+//! | 8 | layout version | creator |
+//! | 16 | slot count | creator |
+//! | 24 | slot payload size | creator |
+//! | 64 | tail (frames published) | producer |
+//! | 72 | sender-closed flag | producer |
+//! | 76 | receiver-parked flag | both |
+//! | 128 | head (frames released) | consumer |
+//! | 136 | receiver-closed flag | consumer |
+//! | 140 | sender-parked flag | both |
+//!
+//! Head and tail are monotonically increasing frame sequence numbers; frame
+//! `seq` lives in slot `seq % slots`. A slot in `head..tail` belongs to the
+//! consumer, every other slot belongs to the producer. Each slot begins with
+//! an 8-byte frame length followed by the payload.
+//!
+//! The parked flags implement the wakeup handshake: a side about to wait
+//! raises its flag and rechecks the cursor, and the other side swaps the flag
+//! back down after moving its cursor, sending a notification byte over the
+//! handshake socket only when it saw the flag raised. All cursor and flag
+//! accesses are `SeqCst`, so either the waiter sees the new cursor or the
+//! mover sees the flag.
+//!
+//! Both cursors live in memory the peer can write, so each side validates
+//! the cursor it reads against its own: a tail that moves backwards or past
+//! the ring capacity, or a head past the tail, is a [`ProtocolViolation`]
+//! rather than an index.
+
+use super::shm::{SharedBytes, SharedRegion};
+use std::io;
+use std::os::fd::{BorrowedFd, OwnedFd};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+const MAGIC: u64 = u64::from_le_bytes(*b"ASUPIPC1");
+const VERSION: u64 = 1;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 8;
+const SLOTS_OFFSET: usize = 16;
+const SLOT_SIZE_OFFSET: usize = 24;
+const TAIL_OFFSET: usize = 64;
+const SENDER_CLOSED_OFFSET: usize = 72;
+const RECEIVER_PARKED_OFFSET: usize = 76;
+const HEAD_OFFSET: usize = 128;
+const RECEIVER_CLOSED_OFFSET: usize = 136;
+const SENDER_PARKED_OFFSET: usize = 140;
+const HEADER_LEN: usize = 192;
+
+const FRAME_HEADER_LEN: usize = 8;
+const SLOT_ALIGN: usize = 64;
+
+/// Shared one-word flags in the ring header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Flag {
+    /// The last sender was dropped.
+    SenderClosed,
+    /// The receiver was dropped.
+    ReceiverClosed,
+    /// The receiver is waiting for a frame and wants a notification.
+    ReceiverParked,
+    /// A sender is waiting for a free slot and wants a notification.
+    SenderParked,
+}
+
+impl Flag {
+    const fn offset(self) -> usize {
+        match self {
+            Self::SenderClosed => SENDER_CLOSED_OFFSET,
+            Self::ReceiverClosed => RECEIVER_CLOSED_OFFSET,
+            Self::ReceiverParked => RECEIVER_PARKED_OFFSET,
+            Self::SenderParked => SENDER_PARKED_OFFSET,
+        }
+    }
+}
+
+/// A frame slot held a length the slot cannot contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct CorruptFrame;
+
+/// The peer left a cursor in a state the ring protocol cannot reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ProtocolViolation(pub(super) &'static str);
+
+/// The frame ring over a shared mapping.
+#[derive(Debug)]
+pub(super) struct Ring {
+    region: SharedRegion,
+    slots: u64,
+    slot_size: usize,
+    stride: usize,
+}
+
+impl Ring {
+    /// Creates and initializes a ring of `slots` frames of up to `slot_size`
+    /// payload bytes each.
+    pub(super) fn create(slots: usize, slot_size: usize) -> io::Result<Self> {
+        let (stride, len) = layout(slots, slot_size).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "IPC ring size overflows")
+        })?;
+        let region = SharedRegion::create(len)?;
+        let ring = Self {
+            region,
+            slots: slots as u64,
+            slot_size,
+            stride,
+        };
+        ring.word(SLOTS_OFFSET)
+            .store(slots as u64, Ordering::Relaxed);
+        ring.word(SLOT_SIZE_OFFSET)
+            .store(slot_size as u64, Ordering::Relaxed);
+        ring.word(VERSION_OFFSET).store(VERSION, Ordering::Relaxed);
+        ring.word(MAGIC_OFFSET).store(MAGIC, Ordering::SeqCst);
+        Ok(ring)
+    }
+
+    /// Maps and validates a ring received from the peer.
+    pub(super) fn open(fd: OwnedFd) -> io::Result<Self> {
+        let region = SharedRegion::open(fd)?;
+        if region.len() < HEADER_LEN {
+            return Err(invalid_ring("IPC ring is smaller than its header"));
+        }
+        let word = |offset| region.atomic_u64(offset).load(Ordering::SeqCst);
+        if word(MAGIC_OFFSET) != MAGIC {
+            return Err(invalid_ring("IPC ring has a bad magic number"));
+        }
+        if word(VERSION_OFFSET) != VERSION {
+            return Err(invalid_ring("IPC ring layout version is unsupported"));
+        }
+        let slots = usize::try_from(word(SLOTS_OFFSET))
+            .map_err(|_| invalid_ring("IPC ring slot count overflows"))?;
+        let slot_size = usize::try_from(word(SLOT_SIZE_OFFSET))
+            .map_err(|_| invalid_ring("IPC ring slot size overflows"))?;
+        let (stride, len) = layout(slots, slot_size)
+            .ok_or_else(|| invalid_ring("IPC ring size overflows"))?;
+        if len > region.len() {
+            return Err(invalid_ring("IPC ring header describes more than the mapping"));
+        }
+        Ok(Self {
+            region,
+            slots: slots as u64,
+            slot_size,
+            stride,
+        })
+    }
+
+    /// Returns the descriptor passed to the peer during the handshake.
+    pub(super) fn fd(&self) -> BorrowedFd<'_> {
+        self.region.fd()
+    }
+
+    /// Returns the number of frame slots.
+    pub(super) const fn slots(&self) -> u64 {
+        self.slots
+    }
+
+    /// Returns the largest payload a single frame can carry.
+    pub(super) const fn slot_size(&self) -> usize {
+        self.slot_size
+    }
+
+    /// Returns the number of frames the consumer has released.
+    pub(super) fn head(&self) -> u64 {
+        self.word(HEAD_OFFSET).load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of frames the producer has published.
+    pub(super) fn tail(&self) -> u64 {
+        self.word(TAIL_OFFSET).load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of published frames not yet released.
+    pub(super) fn in_flight(&self) -> u64 {
+        self.tail().saturating_sub(self.head())
+    }
+
+    /// Returns the number of published frames from `next` on, the consumer's
+    /// next unreceived sequence number.
+    pub(super) fn published(&self, next: u64) -> Result<u64, ProtocolViolation> {
+        let ready = self
+            .tail()
+            .checked_sub(next)
+            .ok_or(ProtocolViolation("producer moved the tail backwards"))?;
+        if ready > self.slots {
+            return Err(ProtocolViolation("producer published past the ring capacity"));
+        }
+        Ok(ready)
+    }
+
+    /// Returns the sequence number of the slot the producer fills next.
+    ///
+    /// The caller holds a slot credit, so a full ring means the consumer
+    /// moved the head backwards.
+    pub(super) fn next_free(&self) -> Result<u64, ProtocolViolation> {
+        let tail = self.tail();
+        let used = tail
+            .checked_sub(self.head())
+            .ok_or(ProtocolViolation("consumer released frames never published"))?;
+        if used >= self.slots || tail == u64::MAX {
+            return Err(ProtocolViolation("consumer moved the head backwards"));
+        }
+        Ok(tail)
+    }
+
+    /// Publishes every frame below `tail` to the consumer.
+    pub(super) fn publish(&self, tail: u64) {
+        self.word(TAIL_OFFSET).store(tail, Ordering::SeqCst);
+    }
+
+    /// Hands every slot below `head` back to the producer.
+    pub(super) fn release(&self, head: u64) {
+        self.word(HEAD_OFFSET).store(head, Ordering::SeqCst);
+    }
+
+    /// Returns a shared flag word.
+    pub(super) fn flag(&self, flag: Flag) -> &AtomicU32 {
+        self.region.atomic_u32(flag.offset())
+    }
+
+    /// Encodes frame `seq` into `scratch`, then copies it into the slot;
+    /// `encode` returns the payload length it wrote, or `None` when the value
+    /// does not fit.
+    ///
+    /// The caller must own slot `seq` (it is below `head + slots` and not yet
+    /// published) and must publish it afterwards.
+    pub(super) fn write_frame(
+        &self,
+        seq: u64,
+        scratch: &mut Vec<u8>,
+        encode: impl FnOnce(&mut [u8]) -> Option<usize>,
+    ) -> Option<usize> {
+        scratch.resize(self.slot_size, 0);
+        let written = encode(scratch.as_mut_slice())?.min(self.slot_size);
+        let offset = self.slot_offset(seq);
+        self.region
+            .write_bytes(offset + FRAME_HEADER_LEN, &scratch[..written])
+            .expect("slot offsets lie inside the validated ring layout");
+        self.word(offset).store(written as u64, Ordering::Relaxed);
+        Some(written)
+    }
+
+    /// Copies the payload of published frame `seq` into `buf`.
+    pub(super) fn read_frame(&self, seq: u64, buf: &mut Vec<u8>) -> Result<(), CorruptFrame> {
+        let frame = self.frame(seq)?;
+        buf.resize(frame.len(), 0);
+        frame.copy_to_slice(buf);
+        Ok(())
+    }
+
+    /// Returns an in-place view of the payload of published frame `seq`.
+    ///
+    /// The view stays valid for the ring's lifetime, but the slot only holds
+    /// this frame until the consumer releases it.
+    pub(super) fn frame(&self, seq: u64) -> Result<SharedBytes<'_>, CorruptFrame> {
+        let offset = self.slot_offset(seq);
+        let len = self.word(offset).load(Ordering::Relaxed);
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.slot_size)
+            .ok_or(CorruptFrame)?;
+        self.region
+            .bytes(offset + FRAME_HEADER_LEN, len)
+            .map_err(|_| CorruptFrame)
+    }
+
+    fn slot_offset(&self, seq: u64) -> usize {
+        // The remainder is below `slots`, which came from a `usize`.
+        HEADER_LEN + (seq % self.slots) as usize * self.stride
+    }
+
+    fn word(&self, offset: usize) -> &std::sync::atomic::AtomicU64 {
+        self.region.atomic_u64(offset)
+    }
+}
+
+/// Returns `(slot stride, mapping length)` for a ring shape.
+fn layout(slots: usize, slot_size: usize) -> Option<(usize, usize)> {
+    if slots == 0 || slot_size == 0 {
+        return None;
+    }
+    let stride = slot_size
+        .checked_add(FRAME_HEADER_LEN)?
+        .checked_next_multiple_of(SLOT_ALIGN)?;
+    let len = stride.checked_mul(slots)?.checked_add(HEADER_LEN)?;
+    Some((stride, len))
+}
+
+fn invalid_ring(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn frames_round_trip_through_reopened_ring() {
+        init_test("frames_round_trip_through_reopened_ring");
+        let producer = Ring::create(2, 16).expect("create ring");
+        let fd = producer.fd().try_clone_to_owned().expect("dup fd");
+        let consumer = Ring::open(fd).expect("open ring");
+        crate::assert_with_log!(consumer.slots() == 2, "slots", 2, consumer.slots());
+
+        let mut scratch = Vec::new();
+        let mut frame = Vec::new();
+        for seq in 0..5_u64 {
+            let payload = seq.to_le_bytes();
+            let written = producer.write_frame(seq, &mut scratch, |slot| {
+                slot[..8].copy_from_slice(&payload);
+                Some(8)
+            });
+            crate::assert_with_log!(written == Some(8), "written", Some(8), written);
+            producer.publish(seq + 1);
+            consumer.read_frame(seq, &mut frame).expect("frame");
+            crate::assert_with_log!(frame == payload, "payload", payload, frame);
+            consumer.release(seq + 1);
+            crate::assert_with_log!(
+                producer.in_flight() == 0,
+                "drained",
+                0,
+                producer.in_flight()
+            );
+        }
+        crate::test_complete!("frames_round_trip_through_reopened_ring");
+    }
+
+    #[test]
+    fn corrupt_length_is_reported_not_read() {
+        init_test("corrupt_length_is_reported_not_read");
+        let ring = Ring::create(1, 16).expect("create ring");
+        ring.word(HEADER_LEN).store(1 << 40, Ordering::SeqCst);
+        let frame = ring.read_frame(0, &mut Vec::new());
+        crate::assert_with_log!(
+            frame == Err(CorruptFrame),
+            "corrupt frame",
+            "CorruptFrame",
+            frame
+        );
+        crate::test_complete!("corrupt_length_is_reported_not_read");
+    }
+
+    #[test]
+    fn peer_cursors_are_validated_not_trusted() {
+        init_test("peer_cursors_are_validated_not_trusted");
+        let ring = Ring::create(4, 16).expect("create ring");
+        ring.publish(2);
+        let ready = ring.published(0);
+        crate::assert_with_log!(ready == Ok(2), "ready", Ok::<u64, ProtocolViolation>(2), ready);
+        let backwards = ring.published(3).is_err();
+        crate::assert_with_log!(backwards, "tail behind consumer", true, backwards);
+        ring.publish(9);
+        let overrun = ring.published(0).is_err();
+        crate::assert_with_log!(overrun, "tail past capacity", true, overrun);
+
+        ring.publish(2);
+        ring.release(3);
+        let ahead = ring.next_free().is_err();
+        crate::assert_with_log!(ahead, "head past tail", true, ahead);
+        ring.release(0);
+        ring.publish(4);
+        let full = ring.next_free().is_err();
+        crate::assert_with_log!(full, "head moved backwards", true, full);
+        ring.publish(u64::MAX);
+        ring.release(u64::MAX - 1);
+        let exhausted = ring.next_free().is_err();
+        crate::assert_with_log!(exhausted, "sequence space exhausted", true, exhausted);
+        crate::test_complete!("peer_cursors_are_validated_not_trusted");
+    }
+
+    #[test]
+    fn zero_sized_shapes_are_rejected() {
+        init_test("zero_sized_shapes_are_rejected");
+        let no_slots = Ring::create(0, 16).is_err();
+        let no_payload = Ring::create(4, 0).is_err();
+        let rejected = no_slots && no_payload;
+        crate::assert_with_log!(rejected, "rejected", true, rejected);
+        crate::test_complete!("zero_sized_shapes_are_rejected");
+    }
+}
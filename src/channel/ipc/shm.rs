@@ -0,0 +1,489 @@
+//! Shared-memory mapping backing the IPC ring.
+//!
+//! This is the only module under [`crate::channel::ipc`] allowed to use
+//! `unsafe`. It creates the anonymous shared-memory object, maps it
+//! `MAP_SHARED`, hands out the header atomics, copies payload bytes in and
+//! out of the mapping, and lends [`SharedBytes`] views that read payload in
+//! place.
+//!
+//! The peer can write any byte of the mapping at any time, so payload bytes
+//! are never borrowed as `&[u8]` or `&mut [u8]`: such a reference would
+//! promise the compiler that nothing else changes the memory while it lives.
+//! Payload access goes through raw-pointer volatile accesses instead. Every
+//! access checks its range against the mapping length and reports a range
+//! outside it as an error, so a corrupted or hostile peer can at worst
+//! scramble header values or payload bytes; it can never steer an access
+//! outside the mapping or panic this process.
+//!
+//! On Linux the object is a `memfd` sealed against resizing before it is
+//! handed to the peer, and the receiving side refuses descriptors that are
+//! not sealed, so a peer cannot truncate the file under a live mapping and
+//! turn later reads into `SIGBUS`. Other Unix targets fall back to an
+//! immediately unlinked POSIX `shm_open` object.
+
+#![allow(unsafe_code)]
+
+use std::fs::File;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, AtomicU64};
+
+/// A read/write `MAP_SHARED` mapping of a shared-memory descriptor.
+pub(super) struct SharedRegion {
+    base: NonNull<u8>,
+    len: usize,
+    fd: OwnedFd,
+}
+
+// SAFETY: the mapping is plain process memory owned by this handle until
+// `Drop` unmaps it. Header fields are only reached through atomics and
+// payload bytes only through volatile copies, so moving the handle to another
+// thread cannot introduce a data race on a Rust reference.
+unsafe impl Send for SharedRegion {}
+// SAFETY: see the `Send` impl above; `&SharedRegion` never hands out a
+// non-atomic reference into the mapping.
+unsafe impl Sync for SharedRegion {}
+
+impl SharedRegion {
+    /// Creates an anonymous shared-memory object of `len` bytes and maps it.
+    pub(super) fn create(len: usize) -> io::Result<Self> {
+        let size = u64::try_from(len)
+            .map_err(|_| invalid_input("shared region length exceeds u64"))?;
+        let file = File::from(anonymous_fd()?);
+        file.set_len(size)?;
+        let fd = OwnedFd::from(file);
+        seal_size(&fd)?;
+        Self::map(fd, len)
+    }
+
+    /// Maps a shared-memory descriptor received from a peer.
+    pub(super) fn open(fd: OwnedFd) -> io::Result<Self> {
+        require_sealed(&fd)?;
+        let file = File::from(fd);
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| invalid_data("shared region does not fit the address space"))?;
+        Self::map(OwnedFd::from(file), len)
+    }
+
+    fn map(fd: OwnedFd, len: usize) -> io::Result<Self> {
+        if len == 0 {
+            return Err(invalid_data("shared region is empty"));
+        }
+        // SAFETY: a null hint lets the kernel pick a fresh, page-aligned range
+        // of `len` bytes backed by a descriptor this function owns; nothing
+        // else aliases the new range, and MAP_FAILED is checked before use.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let base = NonNull::new(ptr.cast::<u8>())
+            .ok_or_else(|| io::Error::other("mmap returned a null mapping"))?;
+        Ok(Self { base, len, fd })
+    }
+
+    /// Returns the mapping length in bytes.
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the descriptor to pass to the peer during the handshake.
+    pub(super) fn fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+
+    /// Returns the 64-bit atomic stored at `offset`.
+    ///
+    /// Word offsets come from the ring layout, which is validated against
+    /// the mapping length at open, so an out-of-range offset is a bug in this
+    /// process rather than peer input.
+    pub(super) fn atomic_u64(&self, offset: usize) -> &AtomicU64 {
+        self.assert_header_word(offset, 8);
+        // SAFETY: the range is in bounds and 8-byte aligned (checked above on
+        // top of the page-aligned base), lives as long as `&self`, and
+        // `AtomicU64` has the layout of `u64`, for which every bit pattern
+        // is valid. Both processes touch this word only through atomics.
+        unsafe { &*self.base.as_ptr().add(offset).cast::<AtomicU64>() }
+    }
+
+    /// Returns the 32-bit atomic stored at `offset`.
+    pub(super) fn atomic_u32(&self, offset: usize) -> &AtomicU32 {
+        self.assert_header_word(offset, 4);
+        // SAFETY: as for `atomic_u64`, with 4-byte size and alignment.
+        unsafe { &*self.base.as_ptr().add(offset).cast::<AtomicU32>() }
+    }
+
+    /// Copies `dst.len()` payload bytes at `offset` out of the mapping.
+    ///
+    /// The peer may be writing the range concurrently; the copy then sees a
+    /// mix of old and new bytes, which the frame decoder must tolerate.
+    pub(super) fn read_bytes(&self, offset: usize, dst: &mut [u8]) -> io::Result<()> {
+        let src = self.range_ptr(offset, dst.len())?;
+        for (index, byte) in dst.iter_mut().enumerate() {
+            // SAFETY: `range_ptr` checked that `offset..offset + dst.len()`
+            // lies inside the live mapping. A volatile read of a `u8` creates
+            // no reference, so concurrent peer writes cannot invalidate one.
+            *byte = unsafe { src.add(index).read_volatile() };
+        }
+        Ok(())
+    }
+
+    /// Returns an in-place view of `len` payload bytes at `offset`, or an
+    /// error if the range leaves the mapping.
+    pub(super) fn bytes(&self, offset: usize, len: usize) -> io::Result<SharedBytes<'_>> {
+        self.range_ptr(offset, len)?;
+        Ok(SharedBytes {
+            region: self,
+            offset,
+            len,
+        })
+    }
+
+    /// Reads the payload byte at `offset`, which a [`SharedBytes`] view has
+    /// already checked against the mapping.
+    fn read_byte(&self, offset: usize) -> u8 {
+        let src = self
+            .range_ptr(offset, 1)
+            .expect("shared bytes view lies inside the mapping");
+        // SAFETY: `range_ptr` checked that `offset` lies inside the live
+        // mapping; a volatile read of a `u8` forms no reference.
+        unsafe { src.read_volatile() }
+    }
+
+    /// Copies `src` into the mapping at `offset`.
+    pub(super) fn write_bytes(&self, offset: usize, src: &[u8]) -> io::Result<()> {
+        let dst = self.range_ptr(offset, src.len())?;
+        for (index, byte) in src.iter().enumerate() {
+            // SAFETY: as for `read_bytes`; the write goes through a raw
+            // pointer into the checked range and forms no reference.
+            unsafe { dst.add(index).write_volatile(*byte) };
+        }
+        Ok(())
+    }
+
+    /// Returns a pointer to `len` bytes at `offset`, or an error if the
+    /// range leaves the mapping.
+    fn range_ptr(&self, offset: usize, len: usize) -> io::Result<*mut u8> {
+        if !self.in_bounds(offset, len, 1) {
+            return Err(invalid_data("shared region access out of bounds"));
+        }
+        // SAFETY: `offset <= self.len`, so the result stays inside (or one
+        // past the end of) the mapping.
+        Ok(unsafe { self.base.as_ptr().add(offset) })
+    }
+
+    fn assert_header_word(&self, offset: usize, size: usize) {
+        assert!(
+            self.in_bounds(offset, size, size),
+            "shared region header word out of bounds: offset={offset} size={size} mapping={}",
+            self.len
+        );
+    }
+
+    fn in_bounds(&self, offset: usize, len: usize, align: usize) -> bool {
+        offset
+            .checked_add(len)
+            .is_some_and(|end| end <= self.len)
+            && offset.is_multiple_of(align)
+    }
+}
+
+/// A borrowed, read-only view of payload bytes inside the shared mapping.
+///
+/// The view reads the mapping in place; nothing is copied until the caller
+/// asks for it with [`copy_to_slice`](Self::copy_to_slice) or
+/// [`to_vec`](Self::to_vec). Because the peer can still write the range, the
+/// view never lends a `&[u8]`: every access is a volatile read, and a peer
+/// that breaks the ring protocol can at worst make two reads of the same
+/// byte disagree.
+#[derive(Clone, Copy)]
+pub struct SharedBytes<'a> {
+    region: &'a SharedRegion,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> SharedBytes<'a> {
+    /// Returns the number of bytes in the view.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads the byte at `index`, or `None` if it is out of range.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<u8> {
+        (index < self.len).then(|| self.region.read_byte(self.offset + index))
+    }
+
+    /// Copies the viewed bytes into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst.len()` differs from [`len`](Self::len), like
+    /// [`slice::copy_from_slice`].
+    pub fn copy_to_slice(&self, dst: &mut [u8]) {
+        assert_eq!(
+            dst.len(),
+            self.len,
+            "destination and shared bytes view have different lengths"
+        );
+        self.region
+            .read_bytes(self.offset, dst)
+            .expect("shared bytes view lies inside the mapping");
+    }
+
+    /// Copies the viewed bytes into a new vector.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.len];
+        self.copy_to_slice(&mut bytes);
+        bytes
+    }
+
+    /// Returns an iterator reading the viewed bytes in place.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + 'a {
+        let (region, offset) = (self.region, self.offset);
+        (offset..offset + self.len).map(move |at| region.read_byte(at))
+    }
+}
+
+impl PartialEq<[u8]> for SharedBytes<'_> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.len == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl PartialEq<&[u8]> for SharedBytes<'_> {
+    fn eq(&self, other: &&[u8]) -> bool {
+        *self == **other
+    }
+}
+
+impl std::fmt::Debug for SharedBytes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Drop for SharedRegion {
+    fn drop(&mut self) {
+        // SAFETY: `base`/`len` describe the mapping created in `map`, and no
+        // borrow handed out by the accessors can outlive `&self`.
+        unsafe {
+            libc::munmap(self.base.as_ptr().cast(), self.len);
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedRegion")
+            .field("len", &self.len)
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn anonymous_fd() -> io::Result<OwnedFd> {
+    // SAFETY: the name is a NUL-terminated literal, and a non-negative return
+    // is a fresh descriptor that nothing else owns yet.
+    unsafe {
+        let fd = libc::memfd_create(
+            c"asupersync-ipc".as_ptr(),
+            libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING,
+        );
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(OwnedFd::from_raw_fd(fd))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn anonymous_fd() -> io::Result<OwnedFd> {
+    use std::sync::atomic::Ordering;
+
+    #[cfg(target_vendor = "apple")]
+    const SHM_MODE: libc::c_uint = 0o600;
+    #[cfg(not(target_vendor = "apple"))]
+    const SHM_MODE: libc::mode_t = 0o600;
+    static NEXT_NAME: AtomicU64 = AtomicU64::new(0);
+
+    loop {
+        // Apple caps shm names at 31 bytes, so keep the prefix short.
+        let name = format!(
+            "/asup-ipc-{}-{}",
+            std::process::id(),
+            NEXT_NAME.fetch_add(1, Ordering::Relaxed)
+        );
+        let name = std::ffi::CString::new(name).map_err(io::Error::other)?;
+        // SAFETY: `name` is NUL-terminated and outlives both calls; a
+        // non-negative descriptor is fresh and owned by nobody else. The name
+        // is unlinked at once so the object dies with its last descriptor.
+        unsafe {
+            let fd = libc::shm_open(
+                name.as_ptr(),
+                libc::O_RDWR | libc::O_CREAT | libc::O_EXCL,
+                SHM_MODE,
+            );
+            if fd < 0 {
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() == Some(libc::EEXIST) {
+                    continue;
+                }
+                return Err(err);
+            }
+            libc::shm_unlink(name.as_ptr());
+            return Ok(OwnedFd::from_raw_fd(fd));
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIZE_SEALS: libc::c_int = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_SEAL;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn seal_size(fd: &OwnedFd) -> io::Result<()> {
+    // SAFETY: `fcntl` on a descriptor this process owns, with integer flags
+    // only; no memory is passed to the kernel.
+    let rc = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_ADD_SEALS, SIZE_SEALS) };
+    if rc < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn seal_size(_fd: &OwnedFd) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn require_sealed(fd: &OwnedFd) -> io::Result<()> {
+    // SAFETY: `fcntl` on a descriptor this process owns, with no arguments
+    // beyond the command.
+    let seals = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GET_SEALS) };
+    if seals < 0 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::EINVAL) {
+            return Err(invalid_data("shared region does not support sealing"));
+        }
+        return Err(err);
+    }
+    if seals & libc::F_SEAL_SHRINK == 0 {
+        return Err(invalid_data("shared region is not sealed against shrinking"));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn require_sealed(_fd: &OwnedFd) -> io::Result<()> {
+    Ok(())
+}
+
+fn invalid_input(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn reopened_descriptor_shares_memory() {
+        init_test("reopened_descriptor_shares_memory");
+        let region = SharedRegion::create(4096).expect("create region");
+        let peer_fd = region.fd().try_clone_to_owned().expect("dup fd");
+        let peer = SharedRegion::open(peer_fd).expect("open region");
+        crate::assert_with_log!(peer.len() == 4096, "peer length", 4096, peer.len());
+
+        region.atomic_u64(64).store(7, Ordering::SeqCst);
+        region.write_bytes(128, b"ring").expect("write payload");
+        let seen = peer.atomic_u64(64).load(Ordering::SeqCst);
+        crate::assert_with_log!(seen == 7, "atomic visible", 7, seen);
+        let mut bytes = [0u8; 4];
+        peer.read_bytes(128, &mut bytes).expect("read payload");
+        crate::assert_with_log!(&bytes == b"ring", "payload visible", "ring", bytes);
+        crate::test_complete!("reopened_descriptor_shares_memory");
+    }
+
+    #[test]
+    fn shared_bytes_view_reads_the_mapping_in_place() {
+        init_test("shared_bytes_view_reads_the_mapping_in_place");
+        let region = SharedRegion::create(4096).expect("create region");
+        let peer_fd = region.fd().try_clone_to_owned().expect("dup fd");
+        let peer = SharedRegion::open(peer_fd).expect("open region");
+
+        region.write_bytes(256, b"old!").expect("write payload");
+        let view = peer.bytes(256, 4).expect("view");
+        crate::assert_with_log!(view == &b"old!"[..], "view", "old!", view);
+        // A later write shows through the same view: nothing was copied.
+        region.write_bytes(256, b"new!").expect("rewrite payload");
+        crate::assert_with_log!(view == &b"new!"[..], "in place", "new!", view);
+        crate::assert_with_log!(view.get(4).is_none(), "bounded", None::<u8>, view.get(4));
+        let copied = view.to_vec();
+        crate::assert_with_log!(copied == b"new!", "copy", "new!", copied);
+
+        let outside = peer.bytes(4094, 4).is_err();
+        crate::assert_with_log!(outside, "view past end", true, outside);
+        crate::test_complete!("shared_bytes_view_reads_the_mapping_in_place");
+    }
+
+    #[test]
+    fn out_of_bounds_payload_access_is_an_error() {
+        init_test("out_of_bounds_payload_access_is_an_error");
+        let region = SharedRegion::create(4096).expect("create region");
+        let mut buf = [0u8; 16];
+        let past_end = region.read_bytes(4090, &mut buf).is_err();
+        crate::assert_with_log!(past_end, "read past end", true, past_end);
+        let overflow = region.write_bytes(usize::MAX, &buf).is_err();
+        crate::assert_with_log!(overflow, "offset overflow", true, overflow);
+        let misaligned = std::panic::catch_unwind(|| region.atomic_u64(4).load(Ordering::SeqCst));
+        crate::assert_with_log!(misaligned.is_err(), "misaligned", true, misaligned.is_err());
+        crate::test_complete!("out_of_bounds_payload_access_is_an_error");
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn unsealed_descriptor_is_rejected() {
+        init_test("unsealed_descriptor_is_rejected");
+        let file = tempfile::tempfile().expect("tempfile");
+        file.set_len(4096).expect("set_len");
+        let fd = OwnedFd::from(file);
+        let err = SharedRegion::open(fd).expect_err("unsealed fd");
+        crate::assert_with_log!(
+            err.kind() == io::ErrorKind::InvalidData,
+            "rejected as invalid data",
+            io::ErrorKind::InvalidData,
+            err.kind()
+        );
+        crate::test_complete!("unsealed_descriptor_is_rejected");
+    }
+}
@@ -30,6 +30,7 @@
 //! | [`oneshot`] | `Sender::reserve(&cx)` then `SendPermit::send(value)` | `Sender::send(&cx, value)` | `Receiver::try_recv()` |
 //! | [`watch`] | Latest-value update; no reservation needed | `Sender::send(value)` | `Receiver::borrow()` after `Receiver::changed(&cx).await` |
 //! | [`session`] tracked wrappers | Tracked reserve/commit with proofs | Tracked `send(...)` helpers | Underlying tracked receiver |
+//! | `ipc` (Unix) | `Sender::reserve(&cx).await` then `SendPermit::send(value)` | `Sender::send(&cx, value).await` | `Receiver::try_recv()` / `Receiver::try_recv_ref()` |
 //!
 //! # Convenience Surface Verdicts
 //!
@@ -71,13 +72,17 @@
 //! - [`oneshot`]: Single-use channel for exactly one value
 //! - [`broadcast`]: Multi-producer, multi-consumer broadcast channel
 //! - [`watch`]: Single-producer, multi-consumer state observation
+//! - `ipc` (Unix only): Same-host interprocess channel over a shared-memory ring
 
 pub mod broadcast;
 pub mod clock_skew;
+pub mod compress;
 pub mod crash;
 pub mod erasure;
 pub mod fault;
 pub mod flow_control_monitor;
+#[cfg(unix)]
+pub mod ipc;
 pub mod mpsc;
 pub mod oneshot;
 pub mod partition;
@@ -78,18 +78,18 @@
 //! its lock-protected atomicity to chase synthetic-benchmark
 //! throughput is a bad trade for the asupersync use-case.
 
-use parking_lot::Mutex;
 use smallvec::SmallVec;
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::task::{Context, Poll, Waker};
 
 use crate::cx::Cx;
 use crate::runtime::reactor::token::{SlabToken, TokenSlab};
 use crate::types::outcome::Outcome;
+use crate::util::loom::sync::Mutex;
+use crate::util::loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 /// Error returned when sending fails.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -570,6 +570,7 @@ impl<T> Sender<T> {
     /// Convenience method: reserve and send in one step.
     #[inline]
     pub async fn send(&self, cx: &Cx, value: T) -> Result<(), SendError<T>> {
+        crate::runtime::chaos::perturb(crate::runtime::chaos::CHANNEL_SEND);
         let result = self.reserve(cx).await;
         match result {
             Ok(permit) => permit.try_send(value),
@@ -624,6 +625,7 @@ impl<T> Sender<T> {
     /// claims or cancels its reservation; a later `try_send` must not steal it.
     #[inline]
     pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        crate::runtime::chaos::perturb(crate::runtime::chaos::CHANNEL_SEND);
         let recv_waker = {
             let mut inner = self.shared.inner.lock();
 
@@ -701,6 +703,23 @@ impl<T> Sender<T> {
         self.shared.capacity
     }
 
+    /// Returns the number of queued messages.
+    ///
+    /// The count can change as soon as the lock is released; senders use it
+    /// as a pacing hint, not as a reservation.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shared.inner.lock().queue.len()
+    }
+
+    /// Returns true if no messages are queued.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shared.inner.lock().queue.is_empty()
+    }
+
     /// Returns an opt-in redacted telemetry snapshot for this MPSC sender.
     #[inline]
     #[must_use]
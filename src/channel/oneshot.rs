@@ -27,6 +27,15 @@
 //! - The commit operation (`send`) either delivers the value or returns it in
 //!   `SendError::Disconnected` if the receiver has already closed
 //!
+//! # Receiver Dropout and Rendezvous
+//!
+//! [`Sender::closed`] resolves once the receiver is dropped, so a worker can
+//! stop early when its caller has gone away. [`rendezvous`] creates a
+//! zero-buffer variant whose async [`RendezvousSender::send`] hands the value
+//! over only while the receiver is polling and completes once it has been
+//! taken; a receiver dropped with the value in flight hands it back in
+//! `SendError::Disconnected`.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -47,7 +56,7 @@
 //! ```
 
 use crate::cx::{CancelWakerToken, Cx};
-use parking_lot::Mutex;
+use crate::util::loom::sync::Mutex;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -185,6 +194,9 @@ struct OneShotInner<T> {
     cancellation_count: u64,
     /// Redacted terminal reason once the channel has closed.
     closed_reason: Option<&'static str>,
+    /// Whether this is a [`rendezvous`] channel, whose sender parks in
+    /// `sender_waker` until the receiver is polling and has taken the value.
+    rendezvous: bool,
 }
 
 impl<T> OneShotInner<T> {
@@ -202,6 +214,7 @@ impl<T> OneShotInner<T> {
             receiver_closed_waker: None,
             cancellation_count: 0,
             closed_reason: None,
+            rendezvous: false,
         }
     }
 
@@ -224,6 +237,17 @@ impl<T> OneShotInner<T> {
         self.waker.take()
     }
 
+    /// Takes the parked rendezvous sender's waker so receiver progress
+    /// (a new waiter, or taking the value) can wake it after unlock.
+    #[inline]
+    fn take_rendezvous_waker(&mut self) -> Option<Waker> {
+        if self.rendezvous {
+            self.sender_waker.take()
+        } else {
+            None
+        }
+    }
+
     /// Records a cancellation or abort event without exposing payloads.
     #[inline]
     fn record_cancellation(&mut self) {
@@ -567,6 +591,39 @@ impl<T> Sender<T> {
             return std::task::Poll::Pending;
         }
     }
+
+    /// Waits until the receiver has gone away.
+    ///
+    /// Resolves once the receiver is dropped, whether before or after it
+    /// received anything. Use this to stop expensive work early when the
+    /// caller waiting on the result has been cancelled:
+    ///
+    /// ```ignore
+    /// let (tx, rx) = oneshot::channel::<Report>();
+    /// // worker
+    /// let mut tx = tx;
+    /// let report = select(build_report(&cx), tx.closed()).await;
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn closed(&mut self) -> ClosedFuture<'_, T> {
+        ClosedFuture { sender: self }
+    }
+}
+
+/// Future returned by [`Sender::closed`].
+#[derive(Debug)]
+pub struct ClosedFuture<'a, T> {
+    sender: &'a mut Sender<T>,
+}
+
+impl<T> Future for ClosedFuture<'_, T> {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.sender.poll_closed(ctx)
+    }
 }
 
 impl<T> Drop for Sender<T> {
@@ -700,6 +757,326 @@ impl<T> Drop for SendPermit<T> {
     }
 }
 
+/// Creates a rendezvous oneshot channel.
+///
+/// Unlike [`channel`], the sender does not deposit its value eagerly: its
+/// [`send`](RendezvousSender::send) future hands the value over only once
+/// the receiver is actually polling [`Receiver::recv`], and completes only
+/// after the receiver has taken it. This gives a zero-buffer handoff, so a
+/// large value is never parked in the channel while the receiver is busy.
+///
+/// The two-phase obligation is preserved on both sides: the sender reserves
+/// (cancel-checked) before handing off, and if the receiver is dropped with
+/// the value in flight, the sender gets it back in
+/// [`SendError::Disconnected`] instead of the value being dropped.
+///
+/// # Example
+///
+/// ```ignore
+/// let (tx, mut rx) = oneshot::rendezvous::<Vec<u8>>();
+/// // producer
+/// tx.send(&cx, buffer).await?; // returns once the consumer took `buffer`
+/// // consumer
+/// let buffer = rx.recv(&cx).await?;
+/// ```
+#[inline]
+#[must_use]
+pub fn rendezvous<T>() -> (RendezvousSender<T>, Receiver<T>) {
+    let (sender, receiver) = channel();
+    sender.inner.lock().rendezvous = true;
+    (RendezvousSender { sender }, receiver)
+}
+
+/// The sending half of a [`rendezvous`] oneshot channel.
+///
+/// # Cancel Safety
+///
+/// Dropping the sender (or its permit, or an unfinished send that has not
+/// handed off yet) closes the channel; the receiver observes `Closed`.
+#[derive(Debug)]
+pub struct RendezvousSender<T> {
+    sender: Sender<T>,
+}
+
+impl<T> RendezvousSender<T> {
+    /// Reserves the channel for a rendezvous send, returning a permit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(SendError::Cancelled(()))` if `cx` is already cancelled;
+    /// the sender is consumed and the receiver observes `Closed`.
+    #[inline]
+    pub fn reserve(self, cx: &Cx) -> Result<RendezvousPermit<T>, SendError<()>> {
+        self.sender
+            .reserve(cx)
+            .map(|permit| RendezvousPermit { permit })
+    }
+
+    /// Reserves and sends in one step, waiting for the receiver.
+    ///
+    /// The returned future resolves to `Ok(())` once the receiver has taken
+    /// the value; see [`RendezvousPermit::send`] for the error cases.
+    #[inline]
+    pub fn send(self, cx: &Cx, value: T) -> RendezvousSend<'_, T> {
+        let inner = Arc::clone(&self.sender.inner);
+        match self.reserve(cx) {
+            Ok(permit) => permit.send(cx, value),
+            Err(_) => RendezvousSend::failed(inner, cx, SendError::Cancelled(value)),
+        }
+    }
+
+    /// Checks if the receiver has been dropped.
+    #[inline]
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Waits until the receiver has gone away. See [`Sender::closed`].
+    #[inline]
+    #[must_use]
+    pub fn closed(&mut self) -> ClosedFuture<'_, T> {
+        self.sender.closed()
+    }
+}
+
+/// A reserved right to perform a [`rendezvous`] send.
+///
+/// Dropping the permit without sending aborts, exactly like
+/// [`SendPermit`].
+#[derive(Debug)]
+pub struct RendezvousPermit<T> {
+    permit: SendPermit<T>,
+}
+
+impl<T> RendezvousPermit<T> {
+    /// Hands `value` to the receiver once it is polling.
+    ///
+    /// # Errors
+    ///
+    /// The returned future resolves to:
+    /// - `Err(SendError::Disconnected(value))` if the receiver is dropped
+    ///   before taking the value, including while it is in flight;
+    /// - `Err(SendError::Cancelled(value))` if `cx` is cancelled before the
+    ///   receiver took the value. The channel then closes.
+    #[inline]
+    pub fn send(self, cx: &Cx, value: T) -> RendezvousSend<'_, T> {
+        RendezvousSend {
+            inner: Arc::clone(&self.permit.inner),
+            cx,
+            permit: Some(self.permit),
+            value: Some(value),
+            result: None,
+            cancel_token: None,
+            completed: false,
+        }
+    }
+
+    /// Aborts the send; the receiver observes `Closed`.
+    #[inline]
+    pub fn abort(self) {
+        self.permit.abort();
+    }
+
+    /// Returns `true` if the receiver has been dropped.
+    #[inline]
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.permit.is_closed()
+    }
+}
+
+/// Future returned by [`RendezvousSender::send`] and
+/// [`RendezvousPermit::send`].
+///
+/// Dropping it before the handoff aborts the send. Dropping it after the
+/// handoff leaves the value committed for the receiver.
+#[derive(Debug)]
+pub struct RendezvousSend<'a, T> {
+    inner: Arc<Mutex<OneShotInner<T>>>,
+    cx: &'a Cx,
+    /// Outstanding reservation; `None` once the value has been handed off.
+    permit: Option<SendPermit<T>>,
+    value: Option<T>,
+    /// Pre-resolved outcome (reservation failed before the future existed).
+    result: Option<Result<(), SendError<T>>>,
+    cancel_token: Option<CancelWakerToken>,
+    completed: bool,
+}
+
+// No field is structurally pinned.
+impl<T> Unpin for RendezvousSend<'_, T> {}
+
+impl<'a, T> RendezvousSend<'a, T> {
+    fn failed(inner: Arc<Mutex<OneShotInner<T>>>, cx: &'a Cx, error: SendError<T>) -> Self {
+        Self {
+            inner,
+            cx,
+            permit: None,
+            value: None,
+            result: Some(Err(error)),
+            cancel_token: None,
+            completed: false,
+        }
+    }
+
+    /// Parks the sender until receiver progress or cancellation wakes it.
+    fn park(&mut self, waker: &Waker) {
+        // Clone outside the channel mutex; RawWaker clones may re-enter.
+        let incoming_waker = waker.clone();
+        let retired_waker = {
+            let mut inner = self.inner.lock();
+            if inner
+                .sender_waker
+                .as_ref()
+                .is_some_and(|stored| stored.will_wake(waker))
+            {
+                Some(incoming_waker)
+            } else {
+                inner.sender_waker.replace(incoming_waker)
+            }
+        };
+        retire_waker_after_unlock(retired_waker);
+        self.cancel_token = Some(self.cx.refresh_cancel_waker(self.cancel_token, waker));
+    }
+
+    fn finish(&mut self, result: Result<(), SendError<T>>) -> Poll<Result<(), SendError<T>>> {
+        self.completed = true;
+        if let Some(token) = self.cancel_token.take() {
+            self.cx.clear_cancel_waker(token);
+        }
+        let retired_waker = self.inner.lock().sender_waker.take();
+        retire_waker_after_unlock(retired_waker);
+        Poll::Ready(result)
+    }
+
+    /// Takes an in-flight value back after cancellation, closing the channel.
+    fn reclaim_cancelled(&mut self) -> Poll<Result<(), SendError<T>>> {
+        let (reclaimed, waker, receiver_closed_waker) = {
+            let mut inner = self.inner.lock();
+            let reclaimed = inner.value.take();
+            if reclaimed.is_some() {
+                inner.record_cancellation();
+                inner.closed_reason = Some("cancelled_send");
+                (
+                    reclaimed,
+                    inner.take_waker(),
+                    inner.receiver_closed_waker.take(),
+                )
+            } else {
+                (None, None, None)
+            }
+        };
+        wake_waker_after_unlock(waker);
+        wake_waker_after_unlock(receiver_closed_waker);
+        match reclaimed {
+            Some(value) => {
+                self.cx
+                    .trace("oneshot::rendezvous cancelled with value in flight");
+                self.finish(Err(SendError::Cancelled(value)))
+            }
+            // The receiver took the value before cancellation was observed.
+            None => self.finish(Ok(())),
+        }
+    }
+}
+
+impl<T> Future for RendezvousSend<'_, T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.completed, "RendezvousSend polled after completion");
+        if let Some(result) = this.result.take() {
+            return this.finish(result);
+        }
+
+        if let Some(permit) = this.permit.take() {
+            // Phase 1: hold the value until the receiver is polling.
+            let (receiver_waiting, receiver_dropped) = {
+                let inner = this.inner.lock();
+                (inner.waker.is_some(), inner.receiver_dropped)
+            };
+            let value = this
+                .value
+                .take()
+                .expect("rendezvous value held until handoff");
+            if receiver_waiting || receiver_dropped {
+                if let Err(error) = permit.send(value) {
+                    return this.finish(Err(error));
+                }
+                this.cx.trace("oneshot::rendezvous value handed off");
+            } else if this.cx.checkpoint().is_err() {
+                permit.abort();
+                this.cx
+                    .trace("oneshot::rendezvous cancelled before handoff");
+                return this.finish(Err(SendError::Cancelled(value)));
+            } else {
+                this.permit = Some(permit);
+                this.value = Some(value);
+                this.park(ctx.waker());
+                // Recheck after registration so a receiver that started
+                // waiting (or left) in between is not missed.
+                let ready = {
+                    let inner = this.inner.lock();
+                    inner.waker.is_some() || inner.receiver_dropped
+                };
+                if ready || this.cx.is_cancel_requested() {
+                    ctx.waker().wake_by_ref();
+                }
+                return Poll::Pending;
+            }
+        }
+
+        // Phase 2: the value is in flight until the receiver takes it.
+        loop {
+            {
+                let mut inner = this.inner.lock();
+                if inner.value.is_none() {
+                    drop(inner);
+                    return this.finish(Ok(()));
+                }
+                if inner.receiver_dropped {
+                    let value = inner.value.take().expect("in-flight rendezvous value");
+                    drop(inner);
+                    this.cx
+                        .trace("oneshot::rendezvous receiver dropped with value in flight");
+                    return this.finish(Err(SendError::Disconnected(value)));
+                }
+            }
+            if this.cx.checkpoint().is_err() {
+                return this.reclaim_cancelled();
+            }
+            if this.cancel_token.is_none()
+                || !this
+                    .inner
+                    .lock()
+                    .sender_waker
+                    .as_ref()
+                    .is_some_and(|stored| stored.will_wake(ctx.waker()))
+            {
+                this.park(ctx.waker());
+                // Loop once more to close the registration race.
+                continue;
+            }
+            return Poll::Pending;
+        }
+    }
+}
+
+impl<T> Drop for RendezvousSend<'_, T> {
+    fn drop(&mut self) {
+        // An unsent permit aborts on drop; a handed-off value stays committed.
+        if let Some(token) = self.cancel_token.take() {
+            self.cx.clear_cancel_waker(token);
+        }
+        if !self.completed {
+            let retired_waker = self.inner.lock().sender_waker.take();
+            retire_waker_after_unlock(retired_waker);
+        }
+    }
+}
+
 /// Future returned by `recv_uninterruptible`.
 pub(crate) struct RecvUninterruptibleFuture<'a, T> {
     receiver: &'a mut Receiver<T>,
@@ -732,12 +1109,14 @@ impl<T> Future for RecvUninterruptibleFuture<'_, T> {
             if let Some(value) = inner.value.take() {
                 let retired_waker = inner.take_waker();
                 let retired_closed_waker = inner.receiver_closed_waker.take();
+                let rendezvous_waker = inner.take_rendezvous_waker();
                 inner.closed_reason = Some("committed");
                 this.waiter_id = None;
                 this.completed = true;
                 drop(inner);
                 retire_waker_after_unlock(retired_waker);
                 wake_waker_after_unlock(retired_closed_waker);
+                wake_waker_after_unlock(rendezvous_waker);
                 return Poll::Ready(Ok(value));
             }
 
@@ -765,12 +1144,14 @@ impl<T> Future for RecvUninterruptibleFuture<'_, T> {
         if let Some(value) = inner.value.take() {
             let retired_waker = inner.take_waker();
             let retired_closed_waker = inner.receiver_closed_waker.take();
+            let rendezvous_waker = inner.take_rendezvous_waker();
             inner.closed_reason = Some("committed");
             this.waiter_id = None;
             this.completed = true;
             drop(inner);
             retire_waker_after_unlock(retired_waker);
             wake_waker_after_unlock(retired_closed_waker);
+            wake_waker_after_unlock(rendezvous_waker);
             retire_waker_after_unlock(incoming_waker);
             return Poll::Ready(Ok(value));
         }
@@ -793,7 +1174,9 @@ impl<T> Future for RecvUninterruptibleFuture<'_, T> {
             ctx.waker(),
             &mut incoming_waker,
         );
+        let rendezvous_waker = inner.take_rendezvous_waker();
         drop(inner);
+        wake_waker_after_unlock(rendezvous_waker);
         retire_waker_after_unlock(retired_waker);
         retire_waker_after_unlock(incoming_waker);
         Poll::Pending
@@ -886,12 +1269,14 @@ impl<T, Caps> RecvFuture<'_, T, Caps> {
         if let Some(value) = inner.value.take() {
             let retired_waker = inner.take_waker();
             let retired_closed_waker = inner.receiver_closed_waker.take();
+            let rendezvous_waker = inner.take_rendezvous_waker();
             inner.closed_reason = Some("committed");
             self.waiter_id = None;
             self.completed = true;
             drop(inner);
             retire_waker_after_unlock(retired_waker);
             wake_waker_after_unlock(retired_closed_waker);
+            wake_waker_after_unlock(rendezvous_waker);
             retire_waker_after_unlock(incoming_waker);
             self.clear_cancel_waker();
             self.cx.trace("oneshot::recv received value");
@@ -951,12 +1336,14 @@ impl<T, Caps> Future for RecvFuture<'_, T, Caps> {
             if let Some(value) = inner.value.take() {
                 let retired_waker = inner.take_waker();
                 let retired_closed_waker = inner.receiver_closed_waker.take();
+                let rendezvous_waker = inner.take_rendezvous_waker();
                 inner.closed_reason = Some("committed");
                 this.waiter_id = None;
                 this.completed = true;
                 drop(inner);
                 retire_waker_after_unlock(retired_waker);
                 wake_waker_after_unlock(retired_closed_waker);
+                wake_waker_after_unlock(rendezvous_waker);
                 this.clear_cancel_waker();
                 this.cx.trace("oneshot::recv received value");
                 return Poll::Ready(Ok(value));
@@ -999,12 +1386,14 @@ impl<T, Caps> Future for RecvFuture<'_, T, Caps> {
         if let Some(value) = inner.value.take() {
             let retired_waker = inner.take_waker();
             let retired_closed_waker = inner.receiver_closed_waker.take();
+            let rendezvous_waker = inner.take_rendezvous_waker();
             inner.closed_reason = Some("committed");
             this.waiter_id = None;
             this.completed = true;
             drop(inner);
             retire_waker_after_unlock(retired_waker);
             wake_waker_after_unlock(retired_closed_waker);
+            wake_waker_after_unlock(rendezvous_waker);
             retire_waker_after_unlock(incoming_waker);
             this.clear_cancel_waker();
             this.cx.trace("oneshot::recv received value");
@@ -1037,7 +1426,9 @@ impl<T, Caps> Future for RecvFuture<'_, T, Caps> {
             ctx.waker(),
             &mut incoming_waker,
         );
+        let rendezvous_waker = inner.take_rendezvous_waker();
         drop(inner);
+        wake_waker_after_unlock(rendezvous_waker);
         retire_waker_after_unlock(retired_waker);
         retire_waker_after_unlock(incoming_waker);
         Poll::Pending
@@ -1128,15 +1519,21 @@ impl<T> Receiver<T> {
     /// - `TryRecvError::Closed` if the sender was dropped without sending
     #[inline]
     pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
-        let (result, retired_waker, retired_closed_waker) = {
+        let (result, retired_waker, retired_closed_waker, rendezvous_waker) = {
             let mut inner = self.inner.lock();
 
             if let Some(value) = inner.value.take() {
                 // Terminal success path: detach stale waiter registration.
                 let retired_waker = inner.take_waker();
                 let retired_closed_waker = inner.receiver_closed_waker.take();
+                let rendezvous_waker = inner.take_rendezvous_waker();
                 inner.closed_reason = Some("committed");
-                (Ok(value), retired_waker, retired_closed_waker)
+                (
+                    Ok(value),
+                    retired_waker,
+                    retired_closed_waker,
+                    rendezvous_waker,
+                )
             } else if inner.is_closed() {
                 // Terminal closed path: detach stale waiter registration.
                 let retired_waker = inner.take_waker();
@@ -1145,12 +1542,15 @@ impl<T> Receiver<T> {
                     Err(TryRecvError::Closed),
                     retired_waker,
                     retired_closed_waker,
+                    None,
                 )
             } else {
-                (Err(TryRecvError::Empty), None, None)
+                (Err(TryRecvError::Empty), None, None, None)
             }
         };
         retire_waker_after_unlock(retired_waker);
+        // A rendezvous sender waits for the value to be taken.
+        wake_waker_after_unlock(rendezvous_waker);
         // A terminal value drain (or observing an already-closed channel) makes
         // `is_closed()` true, so any registered `poll_closed` waiter must be
         // WOKEN, not silently dropped — otherwise it parks forever
@@ -1239,7 +1639,13 @@ impl<T> Drop for Receiver<T> {
             // woken when its awaited predicate actually holds
             // (br-asupersync-0i8acc).
             let retired_closed_waker = inner.receiver_closed_waker.take();
-            let value = inner.value.take();
+            // A rendezvous sender still waiting on delivery reclaims the
+            // in-flight value itself; never drop it here.
+            let value = if inner.rendezvous {
+                None
+            } else {
+                inner.value.take()
+            };
             let closed_after_drop = inner.is_closed();
             (
                 sender_waker,
@@ -7791,4 +8197,223 @@ mod tests {
 
         crate::test_complete!("audit_sender_send_sync_trait_bounds_compliance");
     }
+
+    // =========================================================================
+    // closed() notification and rendezvous handoff
+    // =========================================================================
+
+    #[test]
+    fn sender_closed_fires_on_receiver_drop() {
+        init_test("sender_closed_fires_on_receiver_drop");
+        let (mut tx, rx) = channel::<NonClone>();
+        let wakes = Arc::new(AtomicUsize::new(0));
+        let waker = counting_waker(Arc::clone(&wakes));
+        let mut ctx = Context::from_waker(&waker);
+
+        {
+            let mut closed = tx.closed();
+            let first = Pin::new(&mut closed).poll(&mut ctx);
+            crate::assert_with_log!(
+                first.is_pending(),
+                "pending while rx alive",
+                true,
+                first.is_pending()
+            );
+        }
+        drop(rx);
+        let woken = wakes.load(Ordering::SeqCst);
+        crate::assert_with_log!(woken == 1, "receiver drop wakes closed()", 1, woken);
+        let ready = Pin::new(&mut tx.closed()).poll(&mut ctx).is_ready();
+        crate::assert_with_log!(ready, "closed() resolves after drop", true, ready);
+        crate::assert_with_log!(tx.is_closed(), "is_closed", true, tx.is_closed());
+        crate::test_complete!("sender_closed_fires_on_receiver_drop");
+    }
+
+    #[test]
+    fn rendezvous_closed_fires_on_receiver_drop_before_and_after_handoff() {
+        init_test("rendezvous_closed_fires_on_receiver_drop_before_and_after_handoff");
+        let cx = test_cx();
+        let waker = noop_waker();
+        let mut ctx = Context::from_waker(&waker);
+
+        // Before any send: closed() resolves once the receiver is gone.
+        let (mut tx, rx) = rendezvous::<NonClone>();
+        let pending = Pin::new(&mut tx.closed()).poll(&mut ctx).is_pending();
+        crate::assert_with_log!(pending, "closed pending", true, pending);
+        drop(rx);
+        block_on(tx.closed());
+        crate::assert_with_log!(tx.is_closed(), "closed before send", true, tx.is_closed());
+
+        // After handoff: the in-flight value comes back to the sender.
+        let (tx, mut rx) = rendezvous::<NonClone>();
+        let mut send = tx.send(&cx, NonClone(7));
+        {
+            let mut recv = rx.recv(&cx);
+            let recv_pending = Pin::new(&mut recv).poll(&mut ctx).is_pending();
+            crate::assert_with_log!(recv_pending, "receiver polling", true, recv_pending);
+            let handed_off = Pin::new(&mut send).poll(&mut ctx).is_pending();
+            crate::assert_with_log!(handed_off, "in flight", true, handed_off);
+        }
+        drop(rx);
+        let result = block_on(&mut send);
+        crate::assert_with_log!(
+            matches!(result, Err(SendError::Disconnected(NonClone(7)))),
+            "receiver drop returns in-flight value",
+            "Err(Disconnected(7))",
+            format!("{result:?}")
+        );
+        crate::test_complete!("rendezvous_closed_fires_on_receiver_drop_before_and_after_handoff");
+    }
+
+    #[test]
+    fn rendezvous_send_hands_off_only_to_polling_receiver() {
+        init_test("rendezvous_send_hands_off_only_to_polling_receiver");
+        let cx = test_cx();
+        let waker = noop_waker();
+        let mut ctx = Context::from_waker(&waker);
+        let (tx, mut rx) = rendezvous::<NonClone>();
+
+        let mut send = tx.send(&cx, NonClone(11));
+        let waiting = Pin::new(&mut send).poll(&mut ctx).is_pending();
+        crate::assert_with_log!(waiting, "send waits for receiver", true, waiting);
+        let empty = matches!(rx.try_recv(), Err(TryRecvError::Empty));
+        crate::assert_with_log!(empty, "no value buffered before rendezvous", true, empty);
+
+        let mut recv = rx.recv(&cx);
+        let recv_pending = Pin::new(&mut recv).poll(&mut ctx).is_pending();
+        crate::assert_with_log!(recv_pending, "receiver registers", true, recv_pending);
+        let in_flight = Pin::new(&mut send).poll(&mut ctx).is_pending();
+        crate::assert_with_log!(in_flight, "send pending until taken", true, in_flight);
+
+        let received = Pin::new(&mut recv).poll(&mut ctx);
+        let got = matches!(received, Poll::Ready(Ok(NonClone(11))));
+        crate::assert_with_log!(got, "receiver takes value", true, got);
+        let done = Pin::new(&mut send).poll(&mut ctx);
+        let ok = matches!(done, Poll::Ready(Ok(())));
+        crate::assert_with_log!(ok, "send completes after take", true, ok);
+        crate::test_complete!("rendezvous_send_hands_off_only_to_polling_receiver");
+    }
+
+    #[test]
+    fn rendezvous_failed_send_returns_value() {
+        init_test("rendezvous_failed_send_returns_value");
+        let cx = test_cx();
+
+        let (tx, rx) = rendezvous::<NonClone>();
+        drop(rx);
+        let result = block_on(tx.send(&cx, NonClone(1)));
+        crate::assert_with_log!(
+            matches!(result, Err(SendError::Disconnected(NonClone(1)))),
+            "disconnected before handoff returns value",
+            "Err(Disconnected(1))",
+            format!("{result:?}")
+        );
+
+        let cancelled = test_cx();
+        cancelled.cancel_with(crate::types::CancelKind::User, Some("test cancel"));
+        let (tx, mut rx) = rendezvous::<NonClone>();
+        let result = block_on(tx.send(&cancelled, NonClone(2)));
+        crate::assert_with_log!(
+            matches!(result, Err(SendError::Cancelled(NonClone(2)))),
+            "cancelled send returns value",
+            "Err(Cancelled(2))",
+            format!("{result:?}")
+        );
+        let closed = matches!(rx.try_recv(), Err(TryRecvError::Closed));
+        crate::assert_with_log!(closed, "receiver observes Closed", true, closed);
+        crate::test_complete!("rendezvous_failed_send_returns_value");
+    }
+
+    #[test]
+    fn rendezvous_simultaneous_cancel_does_not_deadlock() {
+        init_test("rendezvous_simultaneous_cancel_does_not_deadlock");
+        let waker = noop_waker();
+        let mut ctx = Context::from_waker(&waker);
+
+        for sender_polls_first in [true, false] {
+            let tx_cx = test_cx();
+            let rx_cx = test_cx();
+            let (tx, mut rx) = rendezvous::<NonClone>();
+            let mut send = tx.send(&tx_cx, NonClone(5));
+            let mut recv = rx.recv(&rx_cx);
+            let _ = Pin::new(&mut recv).poll(&mut ctx);
+
+            tx_cx.cancel_with(crate::types::CancelKind::User, Some("sender cancel"));
+            rx_cx.cancel_with(crate::types::CancelKind::User, Some("receiver cancel"));
+
+            let (send_result, recv_result) = if sender_polls_first {
+                let send_result = block_on(&mut send);
+                (send_result, block_on(&mut recv))
+            } else {
+                let recv_result = block_on(&mut recv);
+                (block_on(&mut send), recv_result)
+            };
+            crate::assert_with_log!(
+                matches!(send_result, Err(SendError::Cancelled(NonClone(5)))),
+                "sender gets its value back",
+                "Err(Cancelled(5))",
+                format!("{send_result:?}")
+            );
+            crate::assert_with_log!(
+                recv_result.is_err(),
+                "receiver terminates without the value",
+                "Err(_)",
+                format!("{recv_result:?}")
+            );
+        }
+        crate::test_complete!("rendezvous_simultaneous_cancel_does_not_deadlock");
+    }
+
+    #[test]
+    fn rendezvous_handoff_follows_receiver_under_lab_interleavings() {
+        init_test("rendezvous_handoff_follows_receiver_under_lab_interleavings");
+        for seed in 0..16_u64 {
+            let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::new(seed));
+            let region = runtime.state.create_root_region(Budget::INFINITE);
+            let (tx, mut rx) = rendezvous::<Vec<u8>>();
+            let log = Arc::new(Mutex::new(Vec::new()));
+
+            let sender_log = Arc::clone(&log);
+            let (sender_id, _) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, async move {
+                    let cx = Cx::current().expect("task Cx");
+                    sender_log.lock().push("send-start");
+                    let result = tx.send(&cx, vec![7_u8; 1024]).await;
+                    sender_log.lock().push("send-done");
+                    result.is_ok()
+                })
+                .expect("create sender");
+            let receiver_log = Arc::clone(&log);
+            let (receiver_id, _) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, async move {
+                    let cx = Cx::current().expect("task Cx");
+                    for _ in 0..(seed % 4) {
+                        crate::runtime::yield_now().await;
+                    }
+                    receiver_log.lock().push("recv-start");
+                    let value = rx.recv(&cx).await.expect("rendezvous value");
+                    receiver_log.lock().push("recv-done");
+                    value.len()
+                })
+                .expect("create receiver");
+            runtime.scheduler.lock().schedule(sender_id, 0);
+            runtime.scheduler.lock().schedule(receiver_id, 0);
+            runtime.run_until_quiescent();
+
+            let log = log.lock().clone();
+            let position = |event: &str| log.iter().position(|entry| *entry == event);
+            crate::assert_with_log!(log.len() == 4, "all events logged", 4, log.len());
+            let ordered = position("recv-start") < position("send-done")
+                && position("send-start") < position("recv-done");
+            crate::assert_with_log!(
+                ordered,
+                "send completes only after receiver polls",
+                true,
+                format!("{seed}: {log:?}")
+            );
+        }
+        crate::test_complete!("rendezvous_handoff_follows_receiver_under_lab_interleavings");
+    }
 }
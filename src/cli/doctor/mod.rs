@@ -5,6 +5,7 @@
 //! `doctor_asupersync` surfaces.
 
 use super::Outputtable;
+use crate::cx::{CAPABILITY_USAGE_LOG_SCHEMA_VERSION, Capability, CapabilitySet};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
@@ -2737,6 +2738,68 @@ impl Outputtable for LockContentionAnalyzerReport {
     }
 }
 
+impl Outputtable for BlockingCallAnalyzerReport {
+    fn human_format(&self) -> String {
+        let mut lines = Vec::new();
+        lines.push(format!("Analyzer version: {}", self.analyzer_version));
+        lines.push(format!("Scanner version: {}", self.scanner_version));
+        lines.push(format!("Correlation id: {}", self.correlation_id));
+        lines.push(format!("Members evaluated: {}", self.member_count));
+        lines.push(format!("Findings: {}", self.finding_count));
+        lines.push(format!("Suppressed: {}", self.suppressed_count));
+        for finding in &self.findings {
+            lines.push(format!(
+                "- [{}] {}:{} `{}` in {} (async: {}) -> use {}",
+                finding.severity,
+                finding.path,
+                finding.line,
+                finding.call,
+                finding.function_name,
+                finding.async_function,
+                finding.replacement
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Outputtable for CapabilityAuditReport {
+    fn human_format(&self) -> String {
+        let mut lines = Vec::new();
+        lines.push(format!("Schema version: {}", self.schema_version));
+        lines.push(format!("Root: {}", self.root));
+        lines.push(format!("Status: {}", self.status));
+        lines.push(format!(
+            "Failures: {}, warnings: {}, usage logs: {}",
+            self.failure_count, self.warning_count, self.usage_log_count
+        ));
+        for member in &self.members {
+            lines.push(format!(
+                "- {} ({}): declared [{}], static [{}], runtime [{}]",
+                member.name,
+                member.manifest_status,
+                member.declared.join(", "),
+                member.static_usage.join(", "),
+                member.runtime_usage.join(", "),
+            ));
+        }
+        for finding in &self.findings {
+            lines.push(format!(
+                "- [{}] {} {} {}: {}",
+                finding.severity,
+                finding.member,
+                finding.kind,
+                finding.capability.as_deref().unwrap_or("-"),
+                finding.evidence.join("; ")
+            ));
+        }
+        for warning in &self.warnings {
+            lines.push(format!("warning: {warning}"));
+        }
+        lines.join("\n")
+    }
+}
+
 impl Outputtable for OperatorModelContract {
     fn human_format(&self) -> String {
         let mut lines = Vec::new();
@@ -3472,6 +3535,174 @@ pub struct LockContentionRuleTrace {
     pub suppressed_reason: Option<String>,
 }
 
+/// Deterministic report of known-blocking calls reachable from async contexts.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct BlockingCallAnalyzerReport {
+    /// Analyzer schema version for downstream consumers.
+    pub analyzer_version: String,
+    /// Scanner version used to generate the source report.
+    pub scanner_version: String,
+    /// Stable correlation identifier for linking analyzer findings.
+    pub correlation_id: String,
+    /// Number of members evaluated.
+    pub member_count: usize,
+    /// Number of emitted findings.
+    pub finding_count: usize,
+    /// Number of findings suppressed by `// doctor:allow(blocking)`.
+    pub suppressed_count: usize,
+    /// Findings ordered by path, line, and call.
+    pub findings: Vec<BlockingCallFinding>,
+    /// Reproducible command pointers for this analyzer pass.
+    pub reproduction_commands: Vec<String>,
+}
+
+/// One blocking call observed inside (or one hop away from) an `async fn`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct BlockingCallFinding {
+    /// Stable finding identifier.
+    pub finding_id: String,
+    /// Path where the call was observed (relative to workspace root).
+    pub path: String,
+    /// 1-based line of the offending call.
+    pub line: usize,
+    /// Function whose body contains the call.
+    pub function_name: String,
+    /// `async fn` that reaches the call (equal to `function_name` for direct hits).
+    pub async_function: String,
+    /// Canonical path of the offending call.
+    pub call: String,
+    /// Call kind (`std_fs`, `std_mutex_lock`, `thread_sleep`).
+    pub call_kind: String,
+    /// Severity (`high` or `medium`).
+    pub severity: String,
+    /// Suggested asupersync replacement.
+    pub replacement: String,
+    /// Deterministic evidence snippets supporting the finding.
+    pub evidence: Vec<String>,
+}
+
+/// Capability manifest of one workspace member (`<member>/capabilities.toml`).
+///
+/// ```toml
+/// version = 1
+/// allow = ["net", "blocking"]
+/// ```
+///
+/// Classes are `net`, `fs`, `process`, `remote`, `blocking`, and
+/// `spawn-into-foreign-region`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityManifest {
+    /// Manifest format version; must be `1`.
+    pub version: u32,
+    /// Capability classes the member may use.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+impl CapabilityManifest {
+    /// Runtime capabilities the manifest declares, as consumed by
+    /// [`CapabilityAudit`](crate::cx::CapabilityAudit).
+    #[must_use]
+    pub fn capability_set(&self) -> CapabilitySet {
+        self.allow
+            .iter()
+            .filter_map(|class| capability_for_class(class))
+            .collect()
+    }
+}
+
+/// Runtime capability-usage log, as written by
+/// [`CapabilityAudit::usage_log_json`](crate::cx::CapabilityAudit::usage_log_json).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CapabilityUsageLog {
+    /// Usage-log schema version.
+    pub schema_version: String,
+    /// Workspace member the log was collected for.
+    pub member: String,
+    /// Audit mode the runtime ran in (`observe` or `enforce`).
+    pub mode: String,
+    /// First use of each capability per region.
+    pub uses: Vec<CapabilityUsageRecord>,
+}
+
+/// One first use recorded in a [`CapabilityUsageLog`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CapabilityUsageRecord {
+    /// Runtime capability name (`net`, `remote_spawn`, ...).
+    pub capability: String,
+    /// Region the use happened in.
+    pub region: String,
+    /// Task that made the use.
+    pub task: String,
+    /// Whether the runtime's manifest declared the capability.
+    pub declared: bool,
+}
+
+/// Deterministic diff of declared capability manifests against observed usage.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CapabilityAuditReport {
+    /// Report schema version for downstream consumers.
+    pub schema_version: String,
+    /// Scanner version used to discover members.
+    pub scanner_version: String,
+    /// Workspace root that was audited.
+    pub root: String,
+    /// Overall status (`pass`, `warn`, or `fail`).
+    pub status: String,
+    /// Number of failure findings.
+    pub failure_count: usize,
+    /// Number of warning findings.
+    pub warning_count: usize,
+    /// Number of runtime usage logs included.
+    pub usage_log_count: usize,
+    /// Per-member declared and observed capability classes.
+    pub members: Vec<CapabilityAuditMember>,
+    /// Findings ordered by member, then capability class.
+    pub findings: Vec<CapabilityAuditFinding>,
+    /// Non-fatal audit warnings (unmatched usage logs, scan warnings).
+    pub warnings: Vec<String>,
+    /// Reproducible command pointers for this audit.
+    pub reproduction_commands: Vec<String>,
+}
+
+/// Declared and observed capability classes of one workspace member.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CapabilityAuditMember {
+    /// Cargo package name.
+    pub name: String,
+    /// Manifest path relative to the workspace root.
+    pub manifest_path: String,
+    /// Manifest state (`declared`, `missing`, or `invalid`).
+    pub manifest_status: String,
+    /// Declared capability classes.
+    pub declared: Vec<String>,
+    /// Capability classes referenced in the member's sources.
+    pub static_usage: Vec<String>,
+    /// Capability classes recorded in the member's runtime usage logs.
+    pub runtime_usage: Vec<String>,
+}
+
+/// One declared-vs-observed mismatch.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CapabilityAuditFinding {
+    /// Stable finding identifier.
+    pub finding_id: String,
+    /// Workspace member the finding is about.
+    pub member: String,
+    /// Capability class, absent for manifest-level findings.
+    pub capability: Option<String>,
+    /// Finding kind (`undeclared_use`, `unused_declaration`,
+    /// `missing_manifest`, or `invalid_manifest`).
+    pub kind: String,
+    /// Severity (`failure` or `warning`).
+    pub severity: String,
+    /// Where the usage was observed (`static`, `runtime`).
+    pub sources: Vec<String>,
+    /// Deterministic evidence lines.
+    pub evidence: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 struct MemberScan {
     member: WorkspaceMember,
@@ -3583,6 +3814,12 @@ const ADVANCED_DIAGNOSTICS_REPORT_VERSION: &str = "doctor-advanced-report-v1";
 const VISUAL_LANGUAGE_VERSION: &str = "doctor-visual-language-v1";
 const INVARIANT_ANALYZER_VERSION: &str = "doctor-invariant-analyzer-v1";
 const LOCK_CONTENTION_ANALYZER_VERSION: &str = "doctor-lock-contention-analyzer-v1";
+const BLOCKING_CALL_ANALYZER_VERSION: &str = "doctor-blocking-call-analyzer-v1";
+const BLOCKING_CALL_ALLOW_MARKER: &str = "doctor:allow(blocking)";
+const CAPABILITY_AUDIT_VERSION: &str = "doctor-capability-audit-v1";
+const CAPABILITY_MANIFEST_FILE: &str = "capabilities.toml";
+const CAPABILITY_MANIFEST_VERSION: u32 = 1;
+const CAPABILITY_AUDIT_EVIDENCE_LIMIT: usize = 5;
 const DOCTOR_EVIDENCE_ANALYZER_VERSION: &str = "doctor-evidence-analyzer-v1";
 const LOCK_ORDER_CANONICAL: &str =
     "E(Config) -> D(Instrumentation) -> B(Regions) -> A(Tasks) -> C(Obligations)";
@@ -3708,7 +3945,7 @@ const EVIDENCE_ADAPTERS: &[EvidenceAdapterDefinition] = &[
         parser: EvidenceParserKind::JsonObject,
     },
 ];
-const SURFACE_MARKERS: [(&str, &[&str]); 12] = [
+const SURFACE_MARKERS: [(&str, &[&str]); 13] = [
     (
         "cx",
         &["&Cx", "asupersync::Cx", "Cx::", "use asupersync::Cx"],
@@ -3771,6 +4008,77 @@ const SURFACE_MARKERS: [(&str, &[&str]); 12] = [
             "commit(",
         ],
     ),
+    (
+        "ambient_rng",
+        &["thread_rng", "OsRng", "rand::rng()", "rand::random"],
+    ),
+];
+
+/// Manifest capability classes and the runtime capability each one audits.
+const CAPABILITY_CLASSES: [(&str, Capability); 6] = [
+    ("net", Capability::Net),
+    ("fs", Capability::Fs),
+    ("process", Capability::Process),
+    ("remote", Capability::RemoteSpawn),
+    ("blocking", Capability::BlockingPool),
+    ("spawn-into-foreign-region", Capability::CrossRegionSpawn),
+];
+
+/// Source markers for each manifest capability class. `Capability::*`
+/// markers count grants through the attenuation API; withholding a
+/// capability with `.without(..)` does not.
+const CAPABILITY_CLASS_MARKERS: [(&str, &[&str]); 6] = [
+    (
+        "net",
+        &[
+            "asupersync::net",
+            "TcpStream",
+            "TcpListener",
+            "TcpSocket",
+            "UdpSocket",
+            "UnixStream",
+            "UnixListener",
+            "UnixDatagram",
+            "Capability::Net",
+        ],
+    ),
+    (
+        "fs",
+        &[
+            "asupersync::fs",
+            "OpenOptions",
+            "File::open(",
+            "File::create(",
+            "Capability::Fs",
+        ],
+    ),
+    (
+        "process",
+        &[
+            "asupersync::process",
+            "Command::new(",
+            "Capability::Process",
+        ],
+    ),
+    (
+        "remote",
+        &[
+            "asupersync::remote",
+            "spawn_remote",
+            "Capability::RemoteSpawn",
+        ],
+    ),
+    ("blocking", &["spawn_blocking", "Capability::BlockingPool"]),
+    (
+        "spawn-into-foreign-region",
+        &[
+            "spawn_in(",
+            "spawn_registered_in(",
+            "spawn_blocking_in(",
+            "spawn_local_in(",
+            "Capability::CrossRegionSpawn",
+        ],
+    ),
 ];
 
 fn payload_field(key: &str, field_type: &str, description: &str) -> PayloadField {
@@ -18796,6 +19104,77 @@ pub fn analyze_workspace_invariants(report: &WorkspaceScanReport) -> InvariantAn
         });
     }
 
+    // Ambient randomness in a member that also drives the lab escapes the
+    // lab seed, so its runs cannot be replayed.
+    let randomness_rule_id = "lab_ambient_randomness";
+    let ambient_rng_members = report
+        .members
+        .iter()
+        .filter(|member| {
+            let surfaces = &member.capability_surfaces;
+            surfaces.iter().any(|surface| surface == "lab")
+                && surfaces.iter().any(|surface| surface == "ambient_rng")
+        })
+        .map(|member| member.name.as_str())
+        .collect::<BTreeSet<_>>();
+    if no_members_discovered {
+        rule_traces.push(InvariantRuleTrace {
+            rule_id: randomness_rule_id.to_string(),
+            correlation_id: correlation_id.clone(),
+            outcome: "suppressed".to_string(),
+            confidence: 100,
+            evidence: vec!["no members discovered in workspace scan".to_string()],
+            suppressed_reason: Some("no members discovered".to_string()),
+        });
+    } else if ambient_rng_members.is_empty() {
+        rule_traces.push(InvariantRuleTrace {
+            rule_id: randomness_rule_id.to_string(),
+            correlation_id: correlation_id.clone(),
+            outcome: "pass".to_string(),
+            confidence: 85,
+            evidence: vec!["no lab member references ambient randomness".to_string()],
+            suppressed_reason: None,
+        });
+    } else {
+        let evidence = report
+            .capability_edges
+            .iter()
+            .filter(|edge| {
+                edge.surface == "ambient_rng"
+                    && ambient_rng_members.contains(edge.member.as_str())
+            })
+            .flat_map(|edge| {
+                edge.sample_files
+                    .iter()
+                    .map(move |file| format!("{}: {file}", edge.member))
+            })
+            .collect::<Vec<_>>();
+        push_finding(
+            randomness_rule_id,
+            "lab_ambient_randomness_detected",
+            "error",
+            format!(
+                "lab-using member(s) draw ambient randomness: {}",
+                ambient_rng_members
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            85,
+            evidence.clone(),
+            "Replace `rand::thread_rng()`/`OsRng` with `cx.rng()` or `cx.rng_named(..)` so draws derive from the lab seed and replay.",
+        );
+        rule_traces.push(InvariantRuleTrace {
+            rule_id: randomness_rule_id.to_string(),
+            correlation_id: correlation_id.clone(),
+            outcome: "fail".to_string(),
+            confidence: 85,
+            evidence,
+            suppressed_reason: None,
+        });
+    }
+
     let lifecycle_rule_id = "scan_lifecycle_events";
     let event_phases = report
         .events
@@ -19390,63 +19769,903 @@ fn classify_lock_hotspot_risk(score: u32) -> &'static str {
     }
 }
 
-fn parse_function_name(line: &str) -> Option<String> {
-    let trimmed = line.trim_start();
-    let function_start = trimmed.find("fn ")?;
-    let remainder = &trimmed[function_start + 3..];
-    let identifier = remainder
-        .chars()
-        .take_while(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
-        .collect::<String>();
-    if identifier.is_empty() {
-        None
-    } else {
-        Some(identifier)
+/// Parse and validate a capability manifest (see [`CapabilityManifest`]).
+///
+/// Rejects unknown keys, versions other than `1`, unknown capability
+/// classes, and classes declared more than once.
+pub fn parse_capability_manifest(text: &str) -> Result<CapabilityManifest, String> {
+    let manifest: CapabilityManifest = toml::from_str(text)
+        .map_err(|err| format!("invalid capability manifest: {err}"))?;
+    if manifest.version != CAPABILITY_MANIFEST_VERSION {
+        return Err(format!(
+            "unsupported capability manifest version {} (expected {CAPABILITY_MANIFEST_VERSION})",
+            manifest.version
+        ));
     }
+    let mut seen = BTreeSet::new();
+    for class in &manifest.allow {
+        if capability_for_class(class).is_none() {
+            let known = CAPABILITY_CLASSES
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!(
+                "unknown capability class `{class}` (expected one of: {known})"
+            ));
+        }
+        if !seen.insert(class.as_str()) {
+            return Err(format!("capability class `{class}` is declared twice"));
+        }
+    }
+    Ok(manifest)
 }
 
-fn sanitize_line_for_lock_analysis(line: &str) -> String {
-    let mut sanitized = String::with_capacity(line.len());
-    let mut chars = line.chars().peekable();
-    let mut in_string = false;
-    let mut in_char = false;
-    let mut escaped = false;
+/// Parse a runtime capability-usage log (see [`CapabilityUsageLog`]).
+pub fn parse_capability_usage_log(text: &str) -> Result<CapabilityUsageLog, String> {
+    let log: CapabilityUsageLog = serde_json::from_str(text)
+        .map_err(|err| format!("invalid capability usage log: {err}"))?;
+    if log.schema_version != CAPABILITY_USAGE_LOG_SCHEMA_VERSION {
+        return Err(format!(
+            "unsupported capability usage log schema `{}` (expected {CAPABILITY_USAGE_LOG_SCHEMA_VERSION})",
+            log.schema_version
+        ));
+    }
+    if let Some(record) = log
+        .uses
+        .iter()
+        .find(|record| class_for_runtime_capability(&record.capability).is_none())
+    {
+        return Err(format!(
+            "unknown runtime capability `{}` in usage log for `{}`",
+            record.capability, log.member
+        ));
+    }
+    Ok(log)
+}
 
-    while let Some(ch) = chars.next() {
-        if in_string {
-            if escaped {
-                escaped = false;
-                sanitized.push(' ');
-                continue;
-            }
-            if ch == '\\' {
-                escaped = true;
-                sanitized.push(' ');
-                continue;
-            }
-            if ch == '"' {
-                in_string = false;
-            }
-            sanitized.push(' ');
+/// Diff each member's capability manifest against its observed capability
+/// usage.
+///
+/// Usage comes from a lexical scan of the member's sources (see
+/// `CAPABILITY_CLASS_MARKERS`) and from any runtime `usage_logs` collected
+/// for it. A used class the manifest does not declare is a failure; a
+/// declared class with no observed use is a warning. A member without a
+/// manifest declares nothing and gets a warning; an invalid manifest is a
+/// failure and its member is not diffed.
+#[must_use]
+pub fn audit_workspace_capabilities(
+    report: &WorkspaceScanReport,
+    usage_logs: &[CapabilityUsageLog],
+) -> CapabilityAuditReport {
+    let root = Path::new(&report.root);
+    let mut warnings = report.warnings.clone();
+    let mut runtime: BTreeMap<&str, BTreeMap<&'static str, Vec<String>>> = BTreeMap::new();
+    for log in usage_logs {
+        if !report.members.iter().any(|member| member.name == log.member) {
+            warnings.push(format!(
+                "capability usage log for `{}` matches no workspace member",
+                log.member
+            ));
             continue;
         }
-
-        if in_char {
-            if escaped {
-                escaped = false;
-                sanitized.push(' ');
+        let observed = runtime.entry(log.member.as_str()).or_default();
+        for record in &log.uses {
+            let Some(class) = class_for_runtime_capability(&record.capability) else {
                 continue;
+            };
+            push_capability_evidence(
+                observed.entry(class).or_default(),
+                format!(
+                    "runtime: {} first used `{}` in {} ({} mode)",
+                    record.task, record.capability, record.region, log.mode
+                ),
+            );
+        }
+    }
+
+    let mut members = Vec::new();
+    let mut findings = Vec::new();
+    for member in &report.members {
+        let manifest_path = Path::new(&member.relative_path)
+            .join(CAPABILITY_MANIFEST_FILE)
+            .display()
+            .to_string();
+        let static_usage = scan_capability_classes(root, member);
+        let runtime_usage = runtime.remove(member.name.as_str()).unwrap_or_default();
+        let mut finding = |capability: Option<&str>,
+                           kind: &str,
+                           severity: &str,
+                           sources: Vec<String>,
+                           evidence: Vec<String>| {
+            findings.push(CapabilityAuditFinding {
+                finding_id: format!(
+                    "capability-{kind}:{}:{}",
+                    member.name,
+                    capability.unwrap_or("manifest")
+                ),
+                member: member.name.clone(),
+                capability: capability.map(str::to_string),
+                kind: kind.to_string(),
+                severity: severity.to_string(),
+                sources,
+                evidence,
+            });
+        };
+
+        let (manifest_status, declared) = match fs::read_to_string(root.join(&manifest_path)) {
+            Ok(text) => match parse_capability_manifest(&text) {
+                Ok(manifest) => ("declared", Some(manifest.allow)),
+                Err(err) => {
+                    finding(
+                        None,
+                        "invalid_manifest",
+                        "failure",
+                        Vec::new(),
+                        vec![format!("{manifest_path}: {err}")],
+                    );
+                    ("invalid", None)
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                finding(
+                    None,
+                    "missing_manifest",
+                    "warning",
+                    Vec::new(),
+                    vec![format!(
+                        "{manifest_path} not found; every capability class is undeclared"
+                    )],
+                );
+                ("missing", Some(Vec::new()))
             }
-            if ch == '\\' {
-                escaped = true;
-                sanitized.push(' ');
-                continue;
+            Err(err) => {
+                finding(
+                    None,
+                    "invalid_manifest",
+                    "failure",
+                    Vec::new(),
+                    vec![format!("{manifest_path}: {err}")],
+                );
+                ("invalid", None)
             }
-            if ch == '\'' {
-                in_char = false;
+        };
+
+        if let Some(declared) = &declared {
+            for (class, _) in CAPABILITY_CLASSES {
+                let is_declared = declared.iter().any(|name| name == class);
+                let mut sources = Vec::new();
+                let mut evidence = Vec::new();
+                for (source, usage) in [("static", &static_usage), ("runtime", &runtime_usage)] {
+                    if let Some(lines) = usage.get(class) {
+                        sources.push(source.to_string());
+                        evidence.extend(lines.iter().cloned());
+                    }
+                }
+                if !sources.is_empty() && !is_declared {
+                    finding(Some(class), "undeclared_use", "failure", sources, evidence);
+                } else if sources.is_empty() && is_declared {
+                    finding(
+                        Some(class),
+                        "unused_declaration",
+                        "warning",
+                        Vec::new(),
+                        vec![format!(
+                            "{manifest_path} declares `{class}` but no use was observed"
+                        )],
+                    );
+                }
             }
-            sanitized.push(' ');
-            continue;
+        }
+
+        members.push(CapabilityAuditMember {
+            name: member.name.clone(),
+            manifest_path,
+            manifest_status: manifest_status.to_string(),
+            declared: declared.unwrap_or_default(),
+            static_usage: static_usage.keys().map(ToString::to_string).collect(),
+            runtime_usage: runtime_usage.keys().map(ToString::to_string).collect(),
+        });
+    }
+
+    let failure_count = findings
+        .iter()
+        .filter(|finding| finding.severity == "failure")
+        .count();
+    let warning_count = findings.len() - failure_count;
+    let status = if failure_count > 0 {
+        "fail"
+    } else if warning_count > 0 {
+        "warn"
+    } else {
+        "pass"
+    };
+    CapabilityAuditReport {
+        schema_version: CAPABILITY_AUDIT_VERSION.to_string(),
+        scanner_version: report.scanner_version.clone(),
+        root: report.root.clone(),
+        status: status.to_string(),
+        failure_count,
+        warning_count,
+        usage_log_count: usage_logs.len(),
+        members,
+        findings,
+        warnings,
+        reproduction_commands: vec![format!(
+            "asupersync doctor capability-audit --root {} --json",
+            report.root
+        )],
+    }
+}
+
+fn capability_for_class(class: &str) -> Option<Capability> {
+    CAPABILITY_CLASSES
+        .iter()
+        .find(|(name, _)| *name == class)
+        .map(|(_, capability)| *capability)
+}
+
+fn class_for_runtime_capability(name: &str) -> Option<&'static str> {
+    CAPABILITY_CLASSES
+        .iter()
+        .find(|(_, capability)| capability.as_str() == name)
+        .map(|(class, _)| *class)
+}
+
+fn push_capability_evidence(evidence: &mut Vec<String>, line: String) {
+    if evidence.len() < CAPABILITY_AUDIT_EVIDENCE_LIMIT {
+        evidence.push(line);
+    }
+}
+
+/// Capability classes referenced in `member`'s sources, with sample
+/// `path:line` evidence. Line comments are skipped.
+fn scan_capability_classes(
+    root: &Path,
+    member: &WorkspaceMember,
+) -> BTreeMap<&'static str, Vec<String>> {
+    let mut usage: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    let source_root = root.join(&member.relative_path).join("src");
+    for file in collect_rust_files(&source_root).unwrap_or_default() {
+        let Ok(source) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let path = relative_to(root, &file);
+        for (index, line) in source.lines().enumerate() {
+            let code = line.trim_start();
+            if code.starts_with("//") {
+                continue;
+            }
+            for (class, markers) in CAPABILITY_CLASS_MARKERS {
+                if let Some(marker) = markers
+                    .iter()
+                    .find(|marker| line_uses_capability_marker(code, marker))
+                {
+                    push_capability_evidence(
+                        usage.entry(class).or_default(),
+                        format!("static: {path}:{}: `{marker}`", index + 1),
+                    );
+                }
+            }
+        }
+    }
+    usage
+}
+
+fn line_uses_capability_marker(code: &str, marker: &str) -> bool {
+    if marker.starts_with("Capability::") {
+        return code
+            .replace(&format!("without({marker})"), "")
+            .contains(marker);
+    }
+    code.contains(marker)
+}
+
+/// Analyze workspace sources for known-blocking calls inside async contexts.
+///
+/// The pass is lexical and favors precision over recall. It flags
+/// `std::thread::sleep`, blocking `std::fs` functions, and `.lock()` on std
+/// mutexes when they appear directly in an `async fn` body, or in a sync
+/// function of the same member that an `async fn` calls directly. Callees are
+/// resolved by name only when the name is unique within the member, so
+/// aliased imports, trait dispatch, macros, closures, and deeper call chains
+/// are not reported. Code inside `spawn_blocking(...)` is exempt, and a
+/// `// doctor:allow(blocking)` comment on the offending line (or on the line
+/// above it) suppresses the finding.
+#[must_use]
+pub fn analyze_workspace_blocking_calls(
+    report: &WorkspaceScanReport,
+) -> BlockingCallAnalyzerReport {
+    let correlation_id = format!(
+        "doctor-blocking-calls:{}:{}:{}",
+        BLOCKING_CALL_ANALYZER_VERSION, report.scanner_version, report.workspace_manifest
+    );
+    let root = Path::new(&report.root);
+    let mut findings = Vec::new();
+    let mut suppressed_count = 0_usize;
+
+    for member in &report.members {
+        let source_root = root.join(&member.relative_path).join("src");
+        let mut functions = Vec::new();
+        for file in collect_rust_files(&source_root).unwrap_or_default() {
+            let Ok(source) = fs::read_to_string(&file) else {
+                continue;
+            };
+            scan_blocking_call_functions(&relative_to(root, &file), &source, &mut functions);
+        }
+
+        let mut name_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for function in &functions {
+            *name_counts.entry(function.name.as_str()).or_default() += 1;
+        }
+
+        // Keyed by call site so a direct hit wins over a one-hop hit, and the
+        // first async caller in (path, line) order wins among one-hop hits.
+        let mut reached: BTreeMap<(String, usize, String), (usize, usize)> = BTreeMap::new();
+        for (index, function) in functions.iter().enumerate() {
+            if !function.is_async {
+                continue;
+            }
+            for site in &function.sites {
+                reached.insert(
+                    (function.path.clone(), site.line, site.call.clone()),
+                    (index, index),
+                );
+            }
+        }
+        for (caller_index, caller) in functions.iter().enumerate() {
+            if !caller.is_async {
+                continue;
+            }
+            for callee_name in &caller.calls {
+                if name_counts.get(callee_name.as_str()).copied() != Some(1) {
+                    continue;
+                }
+                let Some(callee_index) = functions
+                    .iter()
+                    .position(|function| &function.name == callee_name)
+                else {
+                    continue;
+                };
+                let callee = &functions[callee_index];
+                if callee.is_async || callee_index == caller_index {
+                    continue;
+                }
+                for site in &callee.sites {
+                    reached
+                        .entry((callee.path.clone(), site.line, site.call.clone()))
+                        .or_insert((callee_index, caller_index));
+                }
+            }
+        }
+
+        for ((_, line, call), (function_index, async_index)) in reached {
+            let function = &functions[function_index];
+            let async_function = &functions[async_index];
+            let Some(site) = function
+                .sites
+                .iter()
+                .find(|site| site.line == line && site.call == call)
+            else {
+                continue;
+            };
+            if site.suppressed {
+                suppressed_count += 1;
+                continue;
+            }
+            let mut evidence = vec![format!(
+                "{}:{}: {} `{}`",
+                function.path,
+                site.line,
+                site.kind.label(),
+                site.snippet
+            )];
+            if function_index != async_index {
+                evidence.push(format!(
+                    "{}:{}: async fn {} calls {}",
+                    async_function.path, async_function.line, async_function.name, function.name
+                ));
+            }
+            findings.push(BlockingCallFinding {
+                finding_id: String::new(),
+                path: function.path.clone(),
+                line: site.line,
+                function_name: function.name.clone(),
+                async_function: async_function.name.clone(),
+                call: site.call.clone(),
+                call_kind: site.kind.label().to_string(),
+                severity: site.kind.severity().to_string(),
+                replacement: site.replacement.clone(),
+                evidence,
+            });
+        }
+    }
+
+    findings.sort_by(|left, right| {
+        (left.path.as_str(), left.line, left.call.as_str()).cmp(&(
+            right.path.as_str(),
+            right.line,
+            right.call.as_str(),
+        ))
+    });
+    for (index, finding) in findings.iter_mut().enumerate() {
+        finding.finding_id = format!("blocking-call-{:04}", index + 1);
+    }
+
+    BlockingCallAnalyzerReport {
+        analyzer_version: BLOCKING_CALL_ANALYZER_VERSION.to_string(),
+        scanner_version: report.scanner_version.clone(),
+        correlation_id,
+        member_count: report.members.len(),
+        finding_count: findings.len(),
+        suppressed_count,
+        findings,
+        reproduction_commands: vec![
+            format!(
+                "asupersync doctor analyze-blocking-calls --root {}",
+                report.root
+            ),
+            "rch exec -- cargo test --lib cli::doctor::tests::analyze_workspace_blocking_calls_is_deterministic".to_string(),
+        ],
+    }
+}
+
+/// Project blocking-call analyzer findings into a [`CoreDiagnosticsReport`].
+///
+/// The resulting report satisfies [`validate_core_diagnostics_report`] for
+/// slug-like `run_id`/`scenario_id` inputs; empty inputs fall back to
+/// deterministic defaults.
+#[must_use]
+pub fn blocking_call_core_diagnostics_report(
+    analysis: &BlockingCallAnalyzerReport,
+    run_id: &str,
+    scenario_id: &str,
+) -> CoreDiagnosticsReport {
+    let normalized_run_id = if run_id.trim().is_empty() {
+        "run-blocking-calls".to_string()
+    } else {
+        run_id.trim().to_string()
+    };
+    let normalized_scenario_id = if scenario_id.trim().is_empty() {
+        "doctor-blocking-calls".to_string()
+    } else {
+        scenario_id.trim().to_string()
+    };
+    let trace_suffix = normalized_run_id
+        .strip_prefix("run-")
+        .unwrap_or(&normalized_run_id)
+        .to_string();
+    let replay_command = analysis
+        .reproduction_commands
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "asupersync doctor analyze-blocking-calls --root .".to_string());
+    let outcome_class = if analysis.findings.is_empty() {
+        "success"
+    } else {
+        "failed"
+    };
+
+    let mut findings = Vec::with_capacity(analysis.findings.len());
+    let mut evidence = Vec::with_capacity(analysis.findings.len());
+    for finding in &analysis.findings {
+        let evidence_id = format!("evidence-{}", finding.finding_id);
+        findings.push(CoreDiagnosticsFinding {
+            finding_id: finding.finding_id.clone(),
+            title: format!(
+                "Blocking call `{}` reachable from async fn {} at {}:{}; use {}",
+                finding.call,
+                finding.async_function,
+                finding.path,
+                finding.line,
+                finding.replacement
+            ),
+            severity: finding.severity.clone(),
+            status: "open".to_string(),
+            evidence_refs: vec![evidence_id.clone()],
+            command_refs: vec!["command-001".to_string()],
+        });
+        evidence.push(CoreDiagnosticsEvidence {
+            evidence_id,
+            source: "static_scan".to_string(),
+            artifact_pointer: format!("{}:{}", finding.path, finding.line),
+            replay_pointer: replay_command.clone(),
+            outcome_class: "failed".to_string(),
+            franken_trace_id: format!("trace-{trace_suffix}-{}", finding.finding_id),
+        });
+    }
+
+    CoreDiagnosticsReport {
+        schema_version: CORE_DIAGNOSTICS_REPORT_VERSION.to_string(),
+        report_id: "doctor-report-blocking-calls".to_string(),
+        summary: CoreDiagnosticsSummary {
+            status: if findings.is_empty() {
+                "healthy".to_string()
+            } else {
+                "degraded".to_string()
+            },
+            overall_outcome: outcome_class.to_string(),
+            total_findings: findings.len() as u32,
+            critical_findings: findings
+                .iter()
+                .filter(|finding| finding.severity == "critical")
+                .count() as u32,
+        },
+        findings,
+        evidence,
+        commands: vec![CoreDiagnosticsCommand {
+            command_id: "command-001".to_string(),
+            command: replay_command,
+            tool: "asupersync".to_string(),
+            exit_code: i32::from(!analysis.findings.is_empty()),
+            outcome_class: outcome_class.to_string(),
+        }],
+        provenance: CoreDiagnosticsProvenance {
+            run_id: normalized_run_id,
+            scenario_id: normalized_scenario_id,
+            trace_id: format!("trace-{trace_suffix}"),
+            seed: "0".to_string(),
+            generated_by: "doctor_asupersync".to_string(),
+            // The scan is a pure function of the sources; pin the timestamp so
+            // repeated runs produce byte-identical reports.
+            generated_at: "1970-01-01T00:00:00Z".to_string(),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockingCallKind {
+    ThreadSleep,
+    StdFs,
+    StdMutexLock,
+}
+
+impl BlockingCallKind {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::ThreadSleep => "thread_sleep",
+            Self::StdFs => "std_fs",
+            Self::StdMutexLock => "std_mutex_lock",
+        }
+    }
+
+    const fn severity(self) -> &'static str {
+        match self {
+            Self::ThreadSleep | Self::StdMutexLock => "high",
+            Self::StdFs => "medium",
+        }
+    }
+}
+
+/// `std::fs` entry points that block and have an `asupersync::fs` equivalent.
+const BLOCKING_STD_FS_CALLS: &[&str] = &[
+    "File::create",
+    "File::open",
+    "canonicalize",
+    "copy",
+    "create_dir",
+    "create_dir_all",
+    "hard_link",
+    "metadata",
+    "read",
+    "read_dir",
+    "read_link",
+    "read_to_string",
+    "remove_dir",
+    "remove_dir_all",
+    "remove_file",
+    "rename",
+    "set_permissions",
+    "symlink_metadata",
+    "write",
+];
+
+#[derive(Debug, Clone)]
+struct BlockingCallSite {
+    line: usize,
+    call: String,
+    kind: BlockingCallKind,
+    replacement: String,
+    snippet: String,
+    suppressed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct BlockingScanFunction {
+    path: String,
+    name: String,
+    line: usize,
+    is_async: bool,
+    calls: BTreeSet<String>,
+    sites: Vec<BlockingCallSite>,
+}
+
+fn scan_blocking_call_functions(
+    path: &str,
+    source: &str,
+    functions: &mut Vec<BlockingScanFunction>,
+) {
+    let imports_std_fs = source.lines().any(|line| line.trim() == "use std::fs;");
+    let imports_std_mutex = source.contains("std::sync::Mutex")
+        || source.lines().any(|line| {
+            let trimmed = line.trim();
+            trimmed.starts_with("use std::sync::") && trimmed.contains("Mutex")
+        });
+
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut pending: Option<usize> = None;
+    let mut pending_nesting = 0_i32;
+    let mut depth = 0_usize;
+    let mut exempt_depth: Option<usize> = None;
+    let mut allow_next_line = false;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let trimmed = line.trim();
+        let allowed = allow_next_line || trimmed.contains(BLOCKING_CALL_ALLOW_MARKER);
+        allow_next_line = trimmed.starts_with("//") && trimmed.contains(BLOCKING_CALL_ALLOW_MARKER);
+        let sanitized = sanitize_line_for_lock_analysis(trimmed);
+        let code = sanitized.trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        if let Some((name, is_async)) = parse_blocking_scan_fn_header(code) {
+            functions.push(BlockingScanFunction {
+                path: path.to_string(),
+                name,
+                line: line_number,
+                is_async,
+                calls: BTreeSet::new(),
+                sites: Vec::new(),
+            });
+            pending = Some(functions.len() - 1);
+            pending_nesting = 0;
+        }
+
+        if exempt_depth.is_none() && code.contains("spawn_blocking") {
+            exempt_depth = Some(depth);
+        }
+        let line_exempt = exempt_depth.is_some();
+
+        let mut line_function = stack.last().map(|(index, _)| *index);
+        for ch in code.chars() {
+            match ch {
+                '(' | '[' if pending.is_some() => pending_nesting += 1,
+                ')' | ']' if pending.is_some() => pending_nesting -= 1,
+                ';' if pending.is_some() && pending_nesting == 0 => pending = None,
+                '{' => {
+                    if pending_nesting == 0 {
+                        if let Some(index) = pending.take() {
+                            stack.push((index, depth));
+                            line_function = Some(index);
+                        }
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if stack.last().is_some_and(|(_, open)| *open == depth) {
+                        stack.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+        if exempt_depth.is_some_and(|base| depth <= base) {
+            exempt_depth = None;
+        }
+
+        let Some(function_index) = line_function else {
+            continue;
+        };
+        if line_exempt {
+            continue;
+        }
+        let function = &mut functions[function_index];
+        function.calls.extend(extract_blocking_scan_calls(code));
+        for (call, kind, replacement) in
+            detect_blocking_calls(code, imports_std_fs, imports_std_mutex)
+        {
+            function.sites.push(BlockingCallSite {
+                line: line_number,
+                call,
+                kind,
+                replacement,
+                snippet: trimmed.to_string(),
+                suppressed: allowed,
+            });
+        }
+    }
+}
+
+fn parse_blocking_scan_fn_header(code: &str) -> Option<(String, bool)> {
+    for (start, _) in code.match_indices("fn ") {
+        let prefix = &code[..start];
+        if prefix
+            .chars()
+            .next_back()
+            .is_some_and(|ch| !ch.is_whitespace())
+        {
+            continue;
+        }
+        let identifier = code[start + 3..]
+            .trim_start()
+            .chars()
+            .take_while(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
+            .collect::<String>();
+        if identifier.is_empty() {
+            continue;
+        }
+        let is_async = prefix.split_whitespace().any(|token| token == "async");
+        return Some((identifier, is_async));
+    }
+    None
+}
+
+fn detect_blocking_calls(
+    code: &str,
+    imports_std_fs: bool,
+    imports_std_mutex: bool,
+) -> Vec<(String, BlockingCallKind, String)> {
+    let normalized = code
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>();
+    let mut calls = Vec::new();
+
+    for (start, _) in normalized.match_indices("thread::sleep(") {
+        let prefix = &normalized[..start];
+        if prefix.ends_with("std::") || !ends_with_path_char(prefix) {
+            calls.push((
+                "std::thread::sleep".to_string(),
+                BlockingCallKind::ThreadSleep,
+                "asupersync::time::sleep".to_string(),
+            ));
+            break;
+        }
+    }
+
+    for (start, _) in normalized.match_indices("fs::") {
+        let prefix = &normalized[..start];
+        let qualified = prefix.ends_with("std::");
+        if !qualified && (!imports_std_fs || ends_with_path_char(prefix)) {
+            continue;
+        }
+        let rest = &normalized[start + 4..];
+        let Some(open) = rest.find('(') else {
+            continue;
+        };
+        let target = &rest[..open];
+        if BLOCKING_STD_FS_CALLS.contains(&target) {
+            let call = format!("std::fs::{target}");
+            if !calls.iter().any(|(existing, _, _)| existing == &call) {
+                calls.push((
+                    call,
+                    BlockingCallKind::StdFs,
+                    format!("asupersync::fs::{target}"),
+                ));
+            }
+        }
+    }
+
+    if imports_std_mutex && normalized.contains(".lock()") {
+        calls.push((
+            "std::sync::Mutex::lock".to_string(),
+            BlockingCallKind::StdMutexLock,
+            "asupersync::sync::Mutex::lock".to_string(),
+        ));
+    }
+
+    calls
+}
+
+fn ends_with_path_char(prefix: &str) -> bool {
+    prefix
+        .chars()
+        .next_back()
+        .is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == ':')
+}
+
+fn ends_with_word(value: &str, word: &str) -> bool {
+    value.strip_suffix(word).is_some_and(|head| {
+        !head
+            .chars()
+            .next_back()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    })
+}
+
+/// Collects bare `name(...)`, `self.name(...)`, and `Self::name(...)` calls.
+fn extract_blocking_scan_calls(code: &str) -> BTreeSet<String> {
+    let bytes = code.as_bytes();
+    let mut calls = BTreeSet::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if !(bytes[index].is_ascii_alphabetic() || bytes[index] == b'_') {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < bytes.len() && (bytes[index].is_ascii_alphanumeric() || bytes[index] == b'_')
+        {
+            index += 1;
+        }
+        if bytes.get(index) != Some(&b'(') {
+            continue;
+        }
+        let before = &code[..start];
+        let is_call = if let Some(receiver) = before.strip_suffix('.') {
+            ends_with_word(receiver, "self")
+        } else if let Some(path) = before.strip_suffix("::") {
+            ends_with_word(path, "Self")
+        } else {
+            !ends_with_word(before.trim_end(), "fn")
+        };
+        if is_call {
+            calls.insert(code[start..index].to_string());
+        }
+    }
+    calls
+}
+
+fn parse_function_name(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let function_start = trimmed.find("fn ")?;
+    let remainder = &trimmed[function_start + 3..];
+    let identifier = remainder
+        .chars()
+        .take_while(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
+        .collect::<String>();
+    if identifier.is_empty() {
+        None
+    } else {
+        Some(identifier)
+    }
+}
+
+fn sanitize_line_for_lock_analysis(line: &str) -> String {
+    let mut sanitized = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    let mut in_string = false;
+    let mut in_char = false;
+    let mut escaped = false;
+
+    while let Some(ch) = chars.next() {
+        if in_string {
+            if escaped {
+                escaped = false;
+                sanitized.push(' ');
+                continue;
+            }
+            if ch == '\\' {
+                escaped = true;
+                sanitized.push(' ');
+                continue;
+            }
+            if ch == '"' {
+                in_string = false;
+            }
+            sanitized.push(' ');
+            continue;
+        }
+
+        if in_char {
+            if escaped {
+                escaped = false;
+                sanitized.push(' ');
+                continue;
+            }
+            if ch == '\\' {
+                escaped = true;
+                sanitized.push(' ');
+                continue;
+            }
+            if ch == '\'' {
+                in_char = false;
+            }
+            sanitized.push(' ');
+            continue;
         }
 
         if ch == '"' {
@@ -20409,6 +21628,37 @@ members = ["missing_member"]
         );
     }
 
+    #[test]
+    fn analyze_workspace_invariants_flags_ambient_randomness_in_lab_members() {
+        let report = make_single_member_workspace_report(
+            "use asupersync::lab::LabRuntime;\nfn pick(n: usize) -> usize { rand::thread_rng().gen_range(0..n) }\n",
+        );
+        let analysis = analyze_workspace_invariants(&report);
+        let finding = analysis
+            .findings
+            .iter()
+            .find(|finding| finding.rule_id == "lab_ambient_randomness")
+            .expect("ambient randomness finding");
+        assert_eq!(finding.finding_id, "lab_ambient_randomness_detected");
+        assert_eq!(finding.severity, "error");
+        assert_eq!(finding.evidence, vec!["crate_a: crate_a/src/lib.rs"]);
+
+        // Capability randomness, or ambient randomness without the lab, passes.
+        for source in [
+            "use asupersync::lab::LabRuntime;\nfn pick(cx: &Cx) -> u64 { cx.rng().next_u64() }\n",
+            "fn pick() -> u64 { rand::random() }\n",
+        ] {
+            let report = make_single_member_workspace_report(source);
+            let analysis = analyze_workspace_invariants(&report);
+            let trace = analysis
+                .rule_traces
+                .iter()
+                .find(|trace| trace.rule_id == "lab_ambient_randomness")
+                .expect("ambient randomness trace");
+            assert_eq!(trace.outcome, "pass", "{source}");
+        }
+    }
+
     #[test]
     fn analyze_workspace_invariants_is_deterministic() {
         let report = make_single_member_workspace_report(
@@ -20417,7 +21667,7 @@ members = ["missing_member"]
         let first = analyze_workspace_invariants(&report);
         let second = analyze_workspace_invariants(&report);
         assert_eq!(first, second);
-        assert_eq!(first.rule_traces.len(), 5);
+        assert_eq!(first.rule_traces.len(), 6);
         assert!(
             first
                 .rule_traces
@@ -20581,6 +21831,217 @@ impl RuntimeState {
         );
     }
 
+    #[test]
+    fn analyze_workspace_blocking_calls_detects_true_positives() {
+        let report = make_single_member_workspace_report(
+            r#"
+use std::sync::Mutex;
+
+pub async fn sleepy() {
+    std::thread::sleep(std::time::Duration::from_millis(5));
+}
+
+pub async fn loader(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+
+pub async fn guarded(state: &Mutex<u32>) -> u32 {
+    *state.lock().unwrap()
+}
+
+pub async fn indirect() {
+    cleanup_stale_files();
+}
+
+fn cleanup_stale_files() {
+    let _ = std::fs::remove_file("stale.lock");
+}
+
+pub fn sync_only() {
+    std::thread::sleep(std::time::Duration::from_millis(1));
+}
+
+pub async fn offloaded() {
+    spawn_blocking(move || {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    });
+}
+"#,
+        );
+        let analysis = analyze_workspace_blocking_calls(&report);
+        assert_eq!(analysis.analyzer_version, BLOCKING_CALL_ANALYZER_VERSION);
+        assert_eq!(analysis.suppressed_count, 0);
+        let summary = analysis
+            .findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.line,
+                    finding.function_name.as_str(),
+                    finding.async_function.as_str(),
+                    finding.call.as_str(),
+                    finding.severity.as_str(),
+                    finding.replacement.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    5,
+                    "sleepy",
+                    "sleepy",
+                    "std::thread::sleep",
+                    "high",
+                    "asupersync::time::sleep",
+                ),
+                (
+                    9,
+                    "loader",
+                    "loader",
+                    "std::fs::read_to_string",
+                    "medium",
+                    "asupersync::fs::read_to_string",
+                ),
+                (
+                    13,
+                    "guarded",
+                    "guarded",
+                    "std::sync::Mutex::lock",
+                    "high",
+                    "asupersync::sync::Mutex::lock",
+                ),
+                (
+                    21,
+                    "cleanup_stale_files",
+                    "indirect",
+                    "std::fs::remove_file",
+                    "medium",
+                    "asupersync::fs::remove_file",
+                ),
+            ]
+        );
+        assert!(
+            analysis
+                .findings
+                .iter()
+                .all(|finding| finding.path.ends_with("lib.rs"))
+        );
+
+        let core = blocking_call_core_diagnostics_report(
+            &analysis,
+            "run-blocking-smoke",
+            "doctor-blocking-smoke",
+        );
+        validate_core_diagnostics_report(&core, &core_diagnostics_report_contract())
+            .expect("valid core report");
+        assert_eq!(core.summary.status, "degraded");
+        assert_eq!(core.summary.total_findings, 4);
+        assert_eq!(core.findings[0].finding_id, "blocking-call-0001");
+        assert_eq!(core.findings[0].severity, "high");
+    }
+
+    #[test]
+    fn analyze_workspace_blocking_calls_honors_allow_comments() {
+        let report = make_single_member_workspace_report(
+            r"
+pub async fn warmup() {
+    std::thread::sleep(std::time::Duration::from_millis(1)); // doctor:allow(blocking)
+    // doctor:allow(blocking) startup-only config read
+    let _ = std::fs::read(path);
+}
+",
+        );
+        let analysis = analyze_workspace_blocking_calls(&report);
+        assert_eq!(analysis.finding_count, 0);
+        assert_eq!(analysis.suppressed_count, 2);
+    }
+
+    #[test]
+    fn analyze_workspace_blocking_calls_clean_fixture_has_no_findings() {
+        let report = make_single_member_workspace_report(
+            r#"
+use asupersync::fs;
+
+pub async fn reload(cx: &Cx, state: &asupersync::sync::Mutex<u32>) {
+    // std::thread::sleep(Duration::from_secs(1)) would block here.
+    let _text = "std::fs::read_to_string(path)";
+    let _bytes = fs::read(path).await;
+    asupersync::time::sleep(cx.now(), Duration::from_millis(5)).await;
+    let _guard = state.lock(cx).await;
+}
+
+fn blocking_helper_never_called_from_async() {
+    std::thread::sleep(std::time::Duration::from_millis(1));
+}
+"#,
+        );
+        let analysis = analyze_workspace_blocking_calls(&report);
+        assert_eq!(analysis.finding_count, 0);
+        assert_eq!(analysis.suppressed_count, 0);
+        let core = blocking_call_core_diagnostics_report(&analysis, "", "");
+        validate_core_diagnostics_report(&core, &core_diagnostics_report_contract())
+            .expect("valid core report");
+        assert_eq!(core.summary.status, "healthy");
+        assert_eq!(core.summary.overall_outcome, "success");
+    }
+
+    #[test]
+    fn analyze_workspace_blocking_calls_is_deterministic() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path();
+        write_file(
+            &root.join("Cargo.toml"),
+            r#"[workspace]
+members = ["crate_b", "crate_a"]
+"#,
+        );
+        for name in ["crate_a", "crate_b"] {
+            write_file(
+                &root.join(format!("{name}/Cargo.toml")),
+                &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n"),
+            );
+            write_file(
+                &root.join(format!("{name}/src/lib.rs")),
+                r"
+pub async fn second() {
+    std::fs::write(path, bytes).ok();
+}
+
+pub async fn first() {
+    std::thread::sleep(std::time::Duration::from_millis(1));
+}
+",
+            );
+        }
+        let report = scan_workspace(root).expect("scan workspace");
+        let first = analyze_workspace_blocking_calls(&report);
+        let second = analyze_workspace_blocking_calls(&report);
+        assert_eq!(first, second);
+        assert_eq!(first.finding_count, 4);
+        let order = first
+            .findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.finding_id.as_str(),
+                    finding.path.as_str(),
+                    finding.line,
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut sorted = order.clone();
+        sorted.sort_by(|left, right| (left.1, left.2).cmp(&(right.1, right.2)));
+        assert_eq!(order, sorted);
+        assert_eq!(order[0].0, "blocking-call-0001");
+        assert!(order[0].1.starts_with("crate_a"));
+        assert_eq!(
+            blocking_call_core_diagnostics_report(&first, "run-a", "scenario-a"),
+            blocking_call_core_diagnostics_report(&second, "run-a", "scenario-a")
+        );
+    }
+
     #[test]
     fn operator_model_contract_validates() {
         let contract = operator_model_contract();
@@ -103,8 +103,9 @@ pub use completion::{Completable, CompletionItem, Shell, generate_completions};
 pub use doctor::{
     AgentSwarmGitStatus, AgentSwarmProofFrontierItem, AgentSwarmRchStatus,
     AgentSwarmRecommendation, AgentSwarmReservation, AgentSwarmStatusContract,
-    AgentSwarmStatusEvent, AgentSwarmStatusSnapshot, CapabilityEdge, ContractCompatibility,
-    ContractErrorEnvelope, CoreDiagnosticsCommand, CoreDiagnosticsEvidence, CoreDiagnosticsFinding,
+    AgentSwarmStatusEvent, AgentSwarmStatusSnapshot, BlockingCallAnalyzerReport,
+    BlockingCallFinding, CapabilityEdge, ContractCompatibility, ContractErrorEnvelope,
+    CoreDiagnosticsCommand, CoreDiagnosticsEvidence, CoreDiagnosticsFinding,
     CoreDiagnosticsFixture, CoreDiagnosticsProvenance, CoreDiagnosticsReport,
     CoreDiagnosticsReportBundle, CoreDiagnosticsReportContract, CoreDiagnosticsSummary,
     CorrelationPrimitiveSpec, DecisionLoop, DecisionStep, EvidenceIngestionReport,
@@ -118,7 +119,8 @@ pub use doctor::{
     RemediationRiskBand, RemediationRollbackPlan, RuntimeArtifact, ScanEvent, ScreenContract,
     ScreenEngineContract, ScreenExchangeEnvelope, ScreenExchangeRequest, StateTransition,
     StructuredLogEvent, StructuredLoggingContract, WorkspaceMember, WorkspaceScanReport,
-    agent_swarm_status_contract, analyze_workspace_invariants, analyze_workspace_lock_contention,
+    agent_swarm_status_contract, analyze_workspace_blocking_calls, analyze_workspace_invariants,
+    analyze_workspace_lock_contention, blocking_call_core_diagnostics_report,
     build_agent_swarm_status_snapshot, compute_remediation_confidence_score,
     core_diagnostics_report_bundle, core_diagnostics_report_contract,
     core_diagnostics_report_fixtures, emit_lock_contention_structured_events,
@@ -155,13 +157,14 @@ pub mod prelude {
     pub use super::doctor::{
         AgentSwarmGitStatus, AgentSwarmProofFrontierItem, AgentSwarmRchStatus,
         AgentSwarmRecommendation, AgentSwarmReservation, AgentSwarmStatusContract,
-        AgentSwarmStatusEvent, AgentSwarmStatusSnapshot, CapabilityEdge, ContractCompatibility,
-        ContractErrorEnvelope, CoreDiagnosticsCommand, CoreDiagnosticsEvidence,
-        CoreDiagnosticsFinding, CoreDiagnosticsFixture, CoreDiagnosticsProvenance,
-        CoreDiagnosticsReport, CoreDiagnosticsReportBundle, CoreDiagnosticsReportContract,
-        CoreDiagnosticsSummary, CorrelationPrimitiveSpec, DecisionLoop, DecisionStep,
-        EvidenceIngestionReport, EvidenceProvenance, EvidenceRecord, ExchangeOutcome,
-        IngestionEvent, InvariantAnalyzerReport, InvariantFinding, InvariantRuleTrace,
+        AgentSwarmStatusEvent, AgentSwarmStatusSnapshot, BlockingCallAnalyzerReport,
+        BlockingCallFinding, CapabilityEdge, ContractCompatibility, ContractErrorEnvelope,
+        CoreDiagnosticsCommand, CoreDiagnosticsEvidence, CoreDiagnosticsFinding,
+        CoreDiagnosticsFixture, CoreDiagnosticsProvenance, CoreDiagnosticsReport,
+        CoreDiagnosticsReportBundle, CoreDiagnosticsReportContract, CoreDiagnosticsSummary,
+        CorrelationPrimitiveSpec, DecisionLoop, DecisionStep, EvidenceIngestionReport,
+        EvidenceProvenance, EvidenceRecord, ExchangeOutcome, IngestionEvent,
+        InvariantAnalyzerReport, InvariantFinding, InvariantRuleTrace,
         LockContentionAnalyzerReport, LockContentionHotspot, LockContentionRuleTrace,
         LockOrderViolation, LoggingFieldSpec, LoggingFlowSpec, MigrationGuidance,
         OperatorModelContract, OperatorPersona, PayloadField, PayloadSchema, RejectedArtifact,
@@ -171,8 +174,9 @@ pub mod prelude {
         RemediationRiskBand, RemediationRollbackPlan, RuntimeArtifact, ScanEvent, ScreenContract,
         ScreenEngineContract, ScreenExchangeEnvelope, ScreenExchangeRequest, StateTransition,
         StructuredLogEvent, StructuredLoggingContract, WorkspaceMember, WorkspaceScanReport,
-        agent_swarm_status_contract, analyze_workspace_invariants,
-        analyze_workspace_lock_contention, build_agent_swarm_status_snapshot,
+        agent_swarm_status_contract, analyze_workspace_blocking_calls,
+        analyze_workspace_invariants, analyze_workspace_lock_contention,
+        blocking_call_core_diagnostics_report, build_agent_swarm_status_snapshot,
         compute_remediation_confidence_score, core_diagnostics_report_bundle,
         core_diagnostics_report_contract, core_diagnostics_report_fixtures,
         emit_lock_contention_structured_events, emit_structured_log_event, execute_screen_exchange,
@@ -0,0 +1,1028 @@
+//! Deterministic, size-thresholded payload compression.
+//!
+//! [`PayloadCompressor`] compresses a payload only when it is at least
+//! [`CompressionConfig::threshold`] bytes long and passes smaller payloads
+//! through untouched. The decision depends on nothing but the payload and the
+//! configuration, so a lab replay makes the same choices and produces the
+//! same bytes. A payload that does not shrink is sent as-is.
+//!
+//! Payloads travel in a self-describing envelope:
+//!
+//! ```text
+//! [algorithm tag: u8][original length: u64 LE][body]
+//! ```
+//!
+//! The declared original length is checked against
+//! [`CompressionConfig::max_decompressed_len`] before anything is inflated,
+//! and inflation never writes past it, so a hostile envelope cannot expand
+//! into an unbounded allocation.
+//!
+//! LZ4 is compiled in with the `payload-compression` feature and zstd with
+//! `payload-compression-zstd`. Without them only
+//! [`CompressionAlgorithm::None`] is usable and every payload is sent as-is.
+//! Two ends of a link agree on an algorithm with
+//! [`CompressionAlgorithm::negotiate`].
+
+use crate::bytes::Bytes;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Length of the envelope header that precedes the payload body.
+pub const PAYLOAD_HEADER_LEN: usize = 9;
+
+/// Default payload size at or above which payloads are compressed (64 KiB).
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 64 * 1024;
+
+/// Default upper bound on a decompressed payload (64 MiB).
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// Default zstd compression level.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Compression algorithm named by a payload envelope.
+///
+/// Wire tags are stable across builds; whether an algorithm can actually be
+/// used depends on the enabled features (see [`is_available`](Self::is_available)).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CompressionAlgorithm {
+    /// The body is the payload itself.
+    #[default]
+    None,
+    /// LZ4 block compression.
+    Lz4,
+    /// Zstandard compression.
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// Every algorithm, in wire-tag order.
+    pub const ALL: [Self; 3] = [Self::None, Self::Lz4, Self::Zstd];
+
+    /// Returns the envelope tag for this algorithm.
+    #[must_use]
+    pub const fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Lz4 => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    /// Returns the algorithm for an envelope tag.
+    #[must_use]
+    pub const fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::None),
+            1 => Some(Self::Lz4),
+            2 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Returns a stable lowercase name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Lz4 => "lz4",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Returns true if this build can compress and decompress with the
+    /// algorithm.
+    #[must_use]
+    pub const fn is_available(self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Lz4 => cfg!(feature = "payload-compression"),
+            Self::Zstd => cfg!(feature = "payload-compression-zstd"),
+        }
+    }
+
+    /// Returns the compressing algorithms compiled into this build, most
+    /// preferred first.
+    #[must_use]
+    pub fn available() -> Vec<Self> {
+        [Self::Lz4, Self::Zstd]
+            .into_iter()
+            .filter(|algorithm| algorithm.is_available())
+            .collect()
+    }
+
+    /// Picks the first algorithm in `local` that `peer` also offers.
+    ///
+    /// Returns [`None`](Self::None) when the two share nothing, which is
+    /// always the case for a peer that predates payload compression and
+    /// offers an empty list.
+    #[must_use]
+    pub fn negotiate(local: &[Self], peer: &[Self]) -> Self {
+        local
+            .iter()
+            .copied()
+            .find(|algorithm| {
+                *algorithm != Self::None && algorithm.is_available() && peer.contains(algorithm)
+            })
+            .unwrap_or(Self::None)
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error produced while validating or decoding a payload envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompressionError {
+    /// The algorithm is not compiled into this build.
+    Unavailable(CompressionAlgorithm),
+    /// The envelope carries a tag no algorithm uses.
+    UnknownAlgorithm(u8),
+    /// The envelope is shorter than its header.
+    Truncated {
+        /// Length of the envelope received.
+        len: usize,
+    },
+    /// The declared original length exceeds the decompression bound.
+    TooLarge {
+        /// Length the envelope declared.
+        declared: u64,
+        /// Configured upper bound.
+        max: u64,
+    },
+    /// The body did not inflate to the declared original length.
+    LengthMismatch {
+        /// Length the envelope declared.
+        declared: u64,
+        /// Length actually produced.
+        actual: u64,
+    },
+    /// The body is not valid for its algorithm.
+    Corrupt(String),
+}
+
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable(algorithm) => {
+                write!(f, "compression algorithm {algorithm} is not available in this build")
+            }
+            Self::UnknownAlgorithm(tag) => write!(f, "unknown compression algorithm tag {tag}"),
+            Self::Truncated { len } => write!(f, "payload envelope truncated at {len} bytes"),
+            Self::TooLarge { declared, max } => write!(
+                f,
+                "payload declares {declared} decompressed bytes, above the {max}-byte bound"
+            ),
+            Self::LengthMismatch { declared, actual } => write!(
+                f,
+                "payload declared {declared} decompressed bytes but produced {actual}"
+            ),
+            Self::Corrupt(reason) => write!(f, "corrupt compressed payload: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {}
+
+/// Configuration for a [`PayloadCompressor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionConfig {
+    algorithm: CompressionAlgorithm,
+    threshold: usize,
+    max_decompressed_len: usize,
+    zstd_level: i32,
+}
+
+impl CompressionConfig {
+    /// Creates a configuration compressing with `algorithm` at the default
+    /// threshold and bound.
+    #[must_use]
+    pub const fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            algorithm,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+            zstd_level: DEFAULT_ZSTD_LEVEL,
+        }
+    }
+
+    /// Creates a configuration that never compresses. Incoming compressed
+    /// payloads are still decoded if their algorithm is available.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self::new(CompressionAlgorithm::None)
+    }
+
+    /// Creates an LZ4 configuration.
+    #[must_use]
+    pub const fn lz4() -> Self {
+        Self::new(CompressionAlgorithm::Lz4)
+    }
+
+    /// Creates a zstd configuration.
+    #[must_use]
+    pub const fn zstd() -> Self {
+        Self::new(CompressionAlgorithm::Zstd)
+    }
+
+    /// Sets the payload size at or above which payloads are compressed.
+    #[must_use]
+    pub const fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the largest payload a received envelope may inflate to.
+    #[must_use]
+    pub const fn with_max_decompressed_len(mut self, max: usize) -> Self {
+        self.max_decompressed_len = max;
+        self
+    }
+
+    /// Sets the zstd compression level.
+    #[must_use]
+    pub const fn with_zstd_level(mut self, level: i32) -> Self {
+        self.zstd_level = level;
+        self
+    }
+
+    /// Returns the algorithm used for outgoing payloads.
+    #[must_use]
+    pub const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the compression threshold in bytes.
+    #[must_use]
+    pub const fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the decompression bound in bytes.
+    #[must_use]
+    pub const fn max_decompressed_len(&self) -> usize {
+        self.max_decompressed_len
+    }
+
+    /// Returns the zstd compression level.
+    #[must_use]
+    pub const fn zstd_level(&self) -> i32 {
+        self.zstd_level
+    }
+
+    /// Returns the algorithms this side can decode, the configured one first.
+    #[must_use]
+    pub fn offer(&self) -> Vec<CompressionAlgorithm> {
+        let mut offer = Vec::new();
+        if self.algorithm != CompressionAlgorithm::None {
+            offer.push(self.algorithm);
+        }
+        for algorithm in CompressionAlgorithm::available() {
+            if algorithm != self.algorithm {
+                offer.push(algorithm);
+            }
+        }
+        offer
+    }
+
+    /// Checks that the configured algorithm is compiled into this build.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Unavailable`] otherwise.
+    pub const fn validate(&self) -> Result<(), CompressionError> {
+        if self.algorithm.is_available() {
+            Ok(())
+        } else {
+            Err(CompressionError::Unavailable(self.algorithm))
+        }
+    }
+}
+
+impl Default for CompressionConfig {
+    /// LZ4 when it is compiled in, otherwise disabled.
+    fn default() -> Self {
+        if CompressionAlgorithm::Lz4.is_available() {
+            Self::lz4()
+        } else {
+            Self::disabled()
+        }
+    }
+}
+
+/// What the compressor decided for one payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompressionDecision {
+    /// Algorithm the payload was encoded with.
+    pub algorithm: CompressionAlgorithm,
+    /// Length of the original payload.
+    pub original_len: u64,
+    /// Length of the encoded body.
+    pub encoded_len: u64,
+}
+
+impl CompressionDecision {
+    /// Encoded length over original length (1.0 when nothing was saved).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> f64 {
+        if self.original_len == 0 {
+            return 1.0;
+        }
+        self.encoded_len as f64 / self.original_len as f64
+    }
+
+    fn digest(&self) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+        let mut bytes = [0_u8; 17];
+        bytes[0] = self.algorithm.tag();
+        bytes[1..9].copy_from_slice(&self.original_len.to_le_bytes());
+        bytes[9..].copy_from_slice(&self.encoded_len.to_le_bytes());
+        for byte in bytes {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+}
+
+/// A payload in its envelope form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedPayload {
+    algorithm: CompressionAlgorithm,
+    original_len: u64,
+    body: Bytes,
+}
+
+impl CompressedPayload {
+    /// Wraps `data` without compressing it.
+    #[must_use]
+    pub fn uncompressed(data: impl Into<Bytes>) -> Self {
+        let body = data.into();
+        Self {
+            algorithm: CompressionAlgorithm::None,
+            original_len: len_u64(body.len()),
+            body,
+        }
+    }
+
+    /// Returns the algorithm the body is encoded with.
+    #[must_use]
+    pub const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns true if the body is compressed.
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        self.algorithm != CompressionAlgorithm::None
+    }
+
+    /// Returns the declared length of the original payload.
+    #[must_use]
+    pub const fn original_len(&self) -> u64 {
+        self.original_len
+    }
+
+    /// Returns the encoded body.
+    #[must_use]
+    pub const fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Returns the length of the envelope, header included.
+    #[must_use]
+    pub fn encoded_len(&self) -> usize {
+        PAYLOAD_HEADER_LEN + self.body.len()
+    }
+
+    /// Returns the decision that produced this payload.
+    #[must_use]
+    pub fn decision(&self) -> CompressionDecision {
+        CompressionDecision {
+            algorithm: self.algorithm,
+            original_len: self.original_len,
+            encoded_len: len_u64(self.body.len()),
+        }
+    }
+
+    /// Serializes the envelope.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(self.encoded_len());
+        frame.push(self.algorithm.tag());
+        frame.extend_from_slice(&self.original_len.to_le_bytes());
+        frame.extend_from_slice(&self.body);
+        frame
+    }
+
+    /// Parses an envelope without inflating its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Truncated`] for a frame shorter than the
+    /// header, [`CompressionError::UnknownAlgorithm`] for an unknown tag, and
+    /// [`CompressionError::LengthMismatch`] for an uncompressed body whose
+    /// length disagrees with the header.
+    pub fn decode(frame: &[u8]) -> Result<Self, CompressionError> {
+        if frame.len() < PAYLOAD_HEADER_LEN {
+            return Err(CompressionError::Truncated { len: frame.len() });
+        }
+        let algorithm = CompressionAlgorithm::from_tag(frame[0])
+            .ok_or(CompressionError::UnknownAlgorithm(frame[0]))?;
+        let mut len = [0_u8; 8];
+        len.copy_from_slice(&frame[1..PAYLOAD_HEADER_LEN]);
+        let original_len = u64::from_le_bytes(len);
+        let body = &frame[PAYLOAD_HEADER_LEN..];
+        if algorithm == CompressionAlgorithm::None && original_len != len_u64(body.len()) {
+            return Err(CompressionError::LengthMismatch {
+                declared: original_len,
+                actual: len_u64(body.len()),
+            });
+        }
+        Ok(Self {
+            algorithm,
+            original_len,
+            body: Bytes::copy_from_slice(body),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct CompressionCounters {
+    payloads: AtomicU64,
+    compressed: AtomicU64,
+    passthrough: AtomicU64,
+    incompressible: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+    compress_nanos: AtomicU64,
+    decompressed: AtomicU64,
+    decompress_nanos: AtomicU64,
+    rejected: AtomicU64,
+    decision_digest: AtomicU64,
+}
+
+/// Point-in-time view of a compressor's counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionMetrics {
+    /// Payloads passed to the compressor.
+    pub payloads: u64,
+    /// Payloads sent compressed.
+    pub compressed: u64,
+    /// Payloads below the threshold, or with compression disabled.
+    pub passthrough: u64,
+    /// Payloads above the threshold that did not shrink and were sent as-is.
+    pub incompressible: u64,
+    /// Original bytes across all payloads.
+    pub bytes_in: u64,
+    /// Encoded body bytes across all payloads.
+    pub bytes_out: u64,
+    /// Wall-clock time spent compressing.
+    pub compress_nanos: u64,
+    /// Compressed payloads successfully inflated.
+    pub decompressed: u64,
+    /// Wall-clock time spent inflating.
+    pub decompress_nanos: u64,
+    /// Received envelopes rejected as oversized, corrupt or unavailable.
+    pub rejected: u64,
+    /// Order-independent digest of every decision, excluding timings.
+    ///
+    /// Two runs that compress the same payloads with the same configuration
+    /// report the same digest, which makes replay divergence easy to spot.
+    pub decision_digest: u64,
+}
+
+impl CompressionMetrics {
+    /// Encoded bytes over original bytes across all payloads.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> f64 {
+        if self.bytes_in == 0 {
+            return 1.0;
+        }
+        self.bytes_out as f64 / self.bytes_in as f64
+    }
+}
+
+/// Threshold-based payload compressor.
+///
+/// Clones share counters, so the two ends of a channel built from one
+/// compressor report a single set of metrics.
+#[derive(Debug, Clone)]
+pub struct PayloadCompressor {
+    config: CompressionConfig,
+    counters: Arc<CompressionCounters>,
+}
+
+impl PayloadCompressor {
+    /// Creates a compressor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Unavailable`] if the configured algorithm
+    /// is not compiled into this build.
+    pub fn new(config: CompressionConfig) -> Result<Self, CompressionError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            counters: Arc::new(CompressionCounters::default()),
+        })
+    }
+
+    /// Creates a compressor that never compresses.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            config: CompressionConfig::disabled(),
+            counters: Arc::new(CompressionCounters::default()),
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &CompressionConfig {
+        &self.config
+    }
+
+    /// Returns the algorithm a payload of `len` bytes would be compressed
+    /// with, before checking whether compression actually shrinks it.
+    #[must_use]
+    pub fn decide(&self, len: usize) -> CompressionAlgorithm {
+        self.decide_with(self.config.algorithm, len)
+    }
+
+    /// Encodes `payload` with the configured algorithm.
+    #[must_use]
+    pub fn compress(&self, payload: &[u8]) -> CompressedPayload {
+        self.compress_with(self.config.algorithm, payload)
+    }
+
+    /// Encodes `payload` with `algorithm`, typically one negotiated for a
+    /// link. The threshold still applies, and an algorithm that is not
+    /// available falls back to sending the payload as-is.
+    #[must_use]
+    pub fn compress_with(
+        &self,
+        algorithm: CompressionAlgorithm,
+        payload: &[u8],
+    ) -> CompressedPayload {
+        let chosen = self.decide_with(algorithm, payload.len());
+        let counters = &self.counters;
+        let encoded = if chosen == CompressionAlgorithm::None {
+            counters.passthrough.fetch_add(1, Ordering::Relaxed);
+            CompressedPayload::uncompressed(Bytes::copy_from_slice(payload))
+        } else {
+            let started = Instant::now();
+            let body = encode_body(chosen, payload, self.config.zstd_level);
+            counters
+                .compress_nanos
+                .fetch_add(elapsed_nanos(started), Ordering::Relaxed);
+            match body {
+                Some(body) if body.len() < payload.len() => {
+                    counters.compressed.fetch_add(1, Ordering::Relaxed);
+                    CompressedPayload {
+                        algorithm: chosen,
+                        original_len: len_u64(payload.len()),
+                        body: Bytes::from(body),
+                    }
+                }
+                _ => {
+                    counters.incompressible.fetch_add(1, Ordering::Relaxed);
+                    CompressedPayload::uncompressed(Bytes::copy_from_slice(payload))
+                }
+            }
+        };
+        self.record(encoded.decision());
+        encoded
+    }
+
+    /// Inflates a payload, enforcing the decompression bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::TooLarge`] if the declared length exceeds
+    /// the bound, [`CompressionError::Unavailable`] if the algorithm is not
+    /// compiled in, and [`CompressionError::Corrupt`] or
+    /// [`CompressionError::LengthMismatch`] if the body does not inflate to
+    /// exactly the declared length.
+    pub fn decompress(&self, payload: &CompressedPayload) -> Result<Vec<u8>, CompressionError> {
+        let result = self.decompress_bounded(payload);
+        if result.is_err() {
+            self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Parses and inflates a serialized envelope.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`CompressedPayload::decode`] or
+    /// [`decompress`](Self::decompress).
+    pub fn decode(&self, frame: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        let payload = CompressedPayload::decode(frame).inspect_err(|_| {
+            self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+        })?;
+        self.decompress(&payload)
+    }
+
+    /// Returns a snapshot of the counters.
+    #[must_use]
+    pub fn metrics(&self) -> CompressionMetrics {
+        let counters = &self.counters;
+        CompressionMetrics {
+            payloads: counters.payloads.load(Ordering::Relaxed),
+            compressed: counters.compressed.load(Ordering::Relaxed),
+            passthrough: counters.passthrough.load(Ordering::Relaxed),
+            incompressible: counters.incompressible.load(Ordering::Relaxed),
+            bytes_in: counters.bytes_in.load(Ordering::Relaxed),
+            bytes_out: counters.bytes_out.load(Ordering::Relaxed),
+            compress_nanos: counters.compress_nanos.load(Ordering::Relaxed),
+            decompressed: counters.decompressed.load(Ordering::Relaxed),
+            decompress_nanos: counters.decompress_nanos.load(Ordering::Relaxed),
+            rejected: counters.rejected.load(Ordering::Relaxed),
+            decision_digest: counters.decision_digest.load(Ordering::Relaxed),
+        }
+    }
+
+    fn decide_with(&self, algorithm: CompressionAlgorithm, len: usize) -> CompressionAlgorithm {
+        if algorithm.is_available() && len >= self.config.threshold {
+            algorithm
+        } else {
+            CompressionAlgorithm::None
+        }
+    }
+
+    fn record(&self, decision: CompressionDecision) {
+        let counters = &self.counters;
+        counters.payloads.fetch_add(1, Ordering::Relaxed);
+        counters
+            .bytes_in
+            .fetch_add(decision.original_len, Ordering::Relaxed);
+        counters
+            .bytes_out
+            .fetch_add(decision.encoded_len, Ordering::Relaxed);
+        // Wrapping addition commutes, so concurrent senders still produce a
+        // digest that depends only on the set of decisions.
+        let digest = decision.digest();
+        let _ = counters
+            .decision_digest
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(current.wrapping_add(digest))
+            });
+    }
+
+    fn decompress_bounded(&self, payload: &CompressedPayload) -> Result<Vec<u8>, CompressionError> {
+        let declared = payload.original_len;
+        if payload.algorithm == CompressionAlgorithm::None {
+            if declared != len_u64(payload.body.len()) {
+                return Err(CompressionError::LengthMismatch {
+                    declared,
+                    actual: len_u64(payload.body.len()),
+                });
+            }
+            return Ok(payload.body.to_vec());
+        }
+
+        let max = len_u64(self.config.max_decompressed_len);
+        if declared > max {
+            return Err(CompressionError::TooLarge { declared, max });
+        }
+        if !payload.algorithm.is_available() {
+            return Err(CompressionError::Unavailable(payload.algorithm));
+        }
+        let original_len =
+            usize::try_from(declared).map_err(|_| CompressionError::TooLarge { declared, max })?;
+
+        let started = Instant::now();
+        let inflated = decode_body(payload.algorithm, &payload.body, original_len)?;
+        if inflated.len() != original_len {
+            return Err(CompressionError::LengthMismatch {
+                declared,
+                actual: len_u64(inflated.len()),
+            });
+        }
+        let counters = &self.counters;
+        counters.decompressed.fetch_add(1, Ordering::Relaxed);
+        counters
+            .decompress_nanos
+            .fetch_add(elapsed_nanos(started), Ordering::Relaxed);
+        Ok(inflated)
+    }
+}
+
+fn len_u64(len: usize) -> u64 {
+    u64::try_from(len).unwrap_or(u64::MAX)
+}
+
+fn elapsed_nanos(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn encode_body(
+    algorithm: CompressionAlgorithm,
+    payload: &[u8],
+    zstd_level: i32,
+) -> Option<Vec<u8>> {
+    match algorithm {
+        #[cfg(feature = "payload-compression")]
+        CompressionAlgorithm::Lz4 => Some(lz4_flex::block::compress(payload)),
+        #[cfg(feature = "payload-compression-zstd")]
+        CompressionAlgorithm::Zstd => zstd::bulk::compress(payload, zstd_level).ok(),
+        _ => {
+            let _ = (payload, zstd_level);
+            None
+        }
+    }
+}
+
+fn decode_body(
+    algorithm: CompressionAlgorithm,
+    body: &[u8],
+    original_len: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    match algorithm {
+        CompressionAlgorithm::None => Ok(body.to_vec()),
+        // Both decoders allocate at most `original_len` bytes and fail rather
+        // than write past it.
+        #[cfg(feature = "payload-compression")]
+        CompressionAlgorithm::Lz4 => lz4_flex::block::decompress(body, original_len)
+            .map_err(|err| CompressionError::Corrupt(err.to_string())),
+        #[cfg(feature = "payload-compression-zstd")]
+        CompressionAlgorithm::Zstd => zstd::bulk::decompress(body, original_len)
+            .map_err(|err| CompressionError::Corrupt(err.to_string())),
+        #[allow(unreachable_patterns)]
+        unavailable => {
+            let _ = original_len;
+            Err(CompressionError::Unavailable(unavailable))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn compressible(len: usize) -> Vec<u8> {
+        b"asupersync payload "
+            .iter()
+            .copied()
+            .cycle()
+            .take(len)
+            .collect()
+    }
+
+    fn incompressible(len: usize) -> Vec<u8> {
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()[0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn envelope_round_trips_and_rejects_malformed_frames() {
+        init_test("envelope_round_trips_and_rejects_malformed_frames");
+        let payload = CompressedPayload::uncompressed(vec![1_u8, 2, 3]);
+        let frame = payload.encode();
+        crate::assert_with_log!(
+            frame.len() == PAYLOAD_HEADER_LEN + 3,
+            "frame length",
+            PAYLOAD_HEADER_LEN + 3,
+            frame.len()
+        );
+        let decoded = CompressedPayload::decode(&frame).expect("decode");
+        crate::assert_with_log!(decoded == payload, "round trip", payload, decoded);
+
+        let truncated = CompressedPayload::decode(&frame[..4]);
+        let expected = Err(CompressionError::Truncated { len: 4 });
+        crate::assert_with_log!(truncated == expected, "truncated", expected, truncated);
+
+        let mut unknown = frame.clone();
+        unknown[0] = 9;
+        let unknown = CompressedPayload::decode(&unknown);
+        let expected = Err(CompressionError::UnknownAlgorithm(9));
+        crate::assert_with_log!(unknown == expected, "unknown tag", expected, unknown);
+
+        let mut lying = frame;
+        lying[1] = 7;
+        let lying = CompressedPayload::decode(&lying);
+        let expected = Err(CompressionError::LengthMismatch {
+            declared: 7,
+            actual: 3,
+        });
+        crate::assert_with_log!(lying == expected, "raw length mismatch", expected, lying);
+        crate::test_complete!("envelope_round_trips_and_rejects_malformed_frames");
+    }
+
+    #[cfg(feature = "payload-compression")]
+    #[test]
+    fn threshold_boundary_decides_compression() {
+        init_test("threshold_boundary_decides_compression");
+        let compressor = PayloadCompressor::new(CompressionConfig::lz4().with_threshold(1024))
+            .expect("lz4");
+
+        let below = compressor.compress(&compressible(1023));
+        crate::assert_with_log!(
+            !below.is_compressed(),
+            "below threshold passes through",
+            false,
+            below.is_compressed()
+        );
+        let at = compressor.compress(&compressible(1024));
+        crate::assert_with_log!(
+            at.algorithm() == CompressionAlgorithm::Lz4,
+            "at threshold compresses",
+            CompressionAlgorithm::Lz4,
+            at.algorithm()
+        );
+        let restored = compressor.decompress(&at).expect("inflate");
+        crate::assert_with_log!(
+            restored == compressible(1024),
+            "round trip",
+            1024,
+            restored.len()
+        );
+
+        let metrics = compressor.metrics();
+        crate::assert_with_log!(metrics.payloads == 2, "payloads", 2, metrics.payloads);
+        crate::assert_with_log!(metrics.compressed == 1, "compressed", 1, metrics.compressed);
+        crate::assert_with_log!(metrics.passthrough == 1, "passthrough", 1, metrics.passthrough);
+        crate::assert_with_log!(metrics.decompressed == 1, "inflated", 1, metrics.decompressed);
+        let bytes_out = 1023 + at.body().len() as u64;
+        crate::assert_with_log!(metrics.bytes_in == 2047, "bytes in", 2047, metrics.bytes_in);
+        crate::assert_with_log!(
+            metrics.bytes_out == bytes_out,
+            "bytes out",
+            bytes_out,
+            metrics.bytes_out
+        );
+        let ratio = bytes_out as f64 / 2047.0;
+        crate::assert_with_log!(metrics.ratio() == ratio, "ratio", ratio, metrics.ratio());
+        let decision = at.decision();
+        let expected = at.body().len() as f64 / 1024.0;
+        crate::assert_with_log!(
+            decision.ratio() == expected,
+            "decision ratio",
+            expected,
+            decision.ratio()
+        );
+        crate::test_complete!("threshold_boundary_decides_compression");
+    }
+
+    #[cfg(feature = "payload-compression")]
+    #[test]
+    fn incompressible_payload_is_sent_as_is() {
+        init_test("incompressible_payload_is_sent_as_is");
+        let compressor = PayloadCompressor::new(CompressionConfig::lz4().with_threshold(64))
+            .expect("lz4");
+        let data = incompressible(4096);
+        let payload = compressor.compress(&data);
+        crate::assert_with_log!(
+            !payload.is_compressed(),
+            "stored raw",
+            false,
+            payload.is_compressed()
+        );
+        let decision = payload.decision();
+        crate::assert_with_log!(decision.ratio() == 1.0, "ratio", 1.0, decision.ratio());
+        let metrics = compressor.metrics();
+        crate::assert_with_log!(
+            metrics.incompressible == 1,
+            "incompressible",
+            1,
+            metrics.incompressible
+        );
+        let restored = compressor.decompress(&payload).expect("raw");
+        crate::assert_with_log!(restored == data, "raw round trip", data.len(), restored.len());
+        crate::test_complete!("incompressible_payload_is_sent_as_is");
+    }
+
+    #[test]
+    fn oversized_declaration_is_rejected_before_inflating() {
+        init_test("oversized_declaration_is_rejected_before_inflating");
+        let compressor = PayloadCompressor::new(
+            CompressionConfig::disabled().with_max_decompressed_len(1024),
+        )
+        .expect("disabled config is always valid");
+        let mut frame = vec![CompressionAlgorithm::Lz4.tag()];
+        frame.extend_from_slice(&u64::MAX.to_le_bytes());
+        frame.extend_from_slice(&[0x1f, 0, 0, 0]);
+
+        let result = compressor.decode(&frame);
+        let expected = Err(CompressionError::TooLarge {
+            declared: u64::MAX,
+            max: 1024,
+        });
+        crate::assert_with_log!(result == expected, "bomb rejected", expected, result);
+        let rejected = compressor.metrics().rejected;
+        crate::assert_with_log!(rejected == 1, "rejected counted", 1, rejected);
+        crate::test_complete!("oversized_declaration_is_rejected_before_inflating");
+    }
+
+    #[cfg(feature = "payload-compression")]
+    #[test]
+    fn understated_length_cannot_inflate_past_declaration() {
+        init_test("understated_length_cannot_inflate_past_declaration");
+        let compressor = PayloadCompressor::new(CompressionConfig::lz4().with_threshold(0))
+            .expect("lz4");
+        let payload = compressor.compress(&vec![0_u8; 1 << 20]);
+        let mut frame = payload.encode();
+        frame[1..PAYLOAD_HEADER_LEN].copy_from_slice(&16_u64.to_le_bytes());
+
+        let result = compressor.decode(&frame);
+        crate::assert_with_log!(result.is_err(), "inflation bounded", true, result.is_err());
+        crate::test_complete!("understated_length_cannot_inflate_past_declaration");
+    }
+
+    #[test]
+    fn decisions_replay_identically() {
+        init_test("decisions_replay_identically");
+        let inputs = [
+            compressible(10),
+            compressible(4096),
+            incompressible(4096),
+            compressible(70_000),
+        ];
+        let run = || {
+            let compressor =
+                PayloadCompressor::new(CompressionConfig::default().with_threshold(1024))
+                    .expect("default config is valid");
+            let frames: Vec<Vec<u8>> = inputs
+                .iter()
+                .map(|input| compressor.compress(input).encode())
+                .collect();
+            (frames, compressor.metrics().decision_digest)
+        };
+        let (first_frames, first_digest) = run();
+        let (second_frames, second_digest) = run();
+        crate::assert_with_log!(
+            first_frames == second_frames,
+            "identical frames",
+            true,
+            first_frames == second_frames
+        );
+        crate::assert_with_log!(
+            first_digest == second_digest,
+            "identical digest",
+            first_digest,
+            second_digest
+        );
+        crate::test_complete!("decisions_replay_identically");
+    }
+
+    #[test]
+    fn negotiation_falls_back_to_uncompressed() {
+        init_test("negotiation_falls_back_to_uncompressed");
+        let local = CompressionConfig::default().offer();
+        let legacy = CompressionAlgorithm::negotiate(&local, &[]);
+        crate::assert_with_log!(
+            legacy == CompressionAlgorithm::None,
+            "legacy peer",
+            CompressionAlgorithm::None,
+            legacy
+        );
+
+        let shared = CompressionAlgorithm::negotiate(
+            &[CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4],
+            &[CompressionAlgorithm::Lz4],
+        );
+        let expected = if CompressionAlgorithm::Lz4.is_available() {
+            CompressionAlgorithm::Lz4
+        } else {
+            CompressionAlgorithm::None
+        };
+        crate::assert_with_log!(shared == expected, "shared algorithm", expected, shared);
+
+        let unavailable = PayloadCompressor::new(CompressionConfig::zstd()).is_ok();
+        crate::assert_with_log!(
+            unavailable == CompressionAlgorithm::Zstd.is_available(),
+            "zstd availability",
+            CompressionAlgorithm::Zstd.is_available(),
+            unavailable
+        );
+        crate::test_complete!("negotiation_falls_back_to_uncompressed");
+    }
+}
@@ -0,0 +1,523 @@
+//! Bounded-memory streaming JSON.
+//!
+//! Large JSON documents (log exports, bulk API responses) should not have to
+//! be buffered whole before the first element is usable. This module reads
+//! and writes JSON incrementally:
+//!
+//! - [`JsonReader`] is a pull parser over any [`AsyncRead`](crate::io::AsyncRead).
+//!   It yields [`Token`]s one at a time; strings without escapes borrow
+//!   straight from the read buffer.
+//! - [`JsonSliceParser`] is the same parser over an in-memory slice, with
+//!   tokens borrowing from the input.
+//! - [`JsonPath`] and [`Selection`] pick values such as `$.items[*]` out of a
+//!   stream and deserialize each one through serde as it completes.
+//! - [`JsonWriter`] serializes to any [`AsyncWrite`](crate::io::AsyncWrite)
+//!   one element at a time, so huge arrays never exist in memory at once.
+//!
+//! Every input-driven allocation is bounded by [`JsonLimits`]. Limits are
+//! checked while scanning, before the read buffer grows to hold more of an
+//! oversized token, so a hostile peer cannot force more than roughly one
+//! limit plus one read chunk of buffering.
+//!
+//! ```ignore
+//! let mut reader = JsonReader::new(body);
+//! let path: JsonPath = "$.items[*]".parse()?;
+//! let mut items = reader.select(&path);
+//! while let Some(item) = items.next::<Item>().await? {
+//!     process(item);
+//! }
+//! ```
+
+mod path;
+mod reader;
+mod scanner;
+mod writer;
+
+pub use path::{JsonPath, Selection};
+pub use reader::{JsonReader, JsonSliceParser};
+pub use writer::JsonWriter;
+
+use std::io;
+
+/// Default maximum nesting depth for [`JsonLimits`].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Default maximum length, in bytes, of one string or key.
+pub const DEFAULT_MAX_STRING_LEN: usize = 1024 * 1024;
+
+/// Default maximum length, in bytes, of one number literal.
+pub const DEFAULT_MAX_NUMBER_LEN: usize = 256;
+
+/// Default maximum size, in bytes, of one value captured for deserialization.
+pub const DEFAULT_MAX_VALUE_LEN: usize = 16 * 1024 * 1024;
+
+/// Resource limits applied while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    /// Maximum number of simultaneously open objects and arrays.
+    pub max_depth: usize,
+    /// Maximum length of one string or key, in raw (still escaped) bytes.
+    pub max_string_len: usize,
+    /// Maximum length of one number literal.
+    pub max_number_len: usize,
+    /// Maximum number of objects and arrays opened over the whole document.
+    pub max_containers: u64,
+    /// Maximum raw size of one value handed to serde by
+    /// [`JsonReader::read_value`] or a [`Selection`].
+    pub max_value_len: usize,
+}
+
+impl JsonLimits {
+    /// Returns the default limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            max_number_len: DEFAULT_MAX_NUMBER_LEN,
+            max_containers: u64::MAX,
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
+        }
+    }
+
+    /// Sets the maximum nesting depth.
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum string and key length.
+    #[must_use]
+    pub const fn with_max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    /// Sets the maximum number literal length.
+    #[must_use]
+    pub const fn with_max_number_len(mut self, max_number_len: usize) -> Self {
+        self.max_number_len = max_number_len;
+        self
+    }
+
+    /// Sets the maximum number of objects and arrays per document.
+    #[must_use]
+    pub const fn with_max_containers(mut self, max_containers: u64) -> Self {
+        self.max_containers = max_containers;
+        self
+    }
+
+    /// Sets the maximum size of one captured value.
+    #[must_use]
+    pub const fn with_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = max_value_len;
+        self
+    }
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One JSON token.
+///
+/// `Key` and `String` borrow from the parser's input when the string has no
+/// escapes, and from a scratch buffer otherwise. `Number` is the literal
+/// text, left for the caller to parse at whatever precision it needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// `{`
+    BeginObject,
+    /// `}`
+    EndObject,
+    /// `[`
+    BeginArray,
+    /// `]`
+    EndArray,
+    /// An object key.
+    Key(&'a str),
+    /// A string value.
+    String(&'a str),
+    /// A number literal.
+    Number(&'a str),
+    /// `true` or `false`.
+    Bool(bool),
+    /// `null`
+    Null,
+}
+
+/// Errors produced by the streaming JSON types.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The input is not valid JSON.
+    Syntax {
+        /// Byte offset of the offending input.
+        offset: u64,
+        /// What was wrong.
+        reason: &'static str,
+    },
+    /// The input ended inside a value.
+    UnexpectedEof {
+        /// Byte offset where the input ended.
+        offset: u64,
+    },
+    /// Nesting exceeded [`JsonLimits::max_depth`].
+    DepthLimitExceeded {
+        /// The configured limit.
+        limit: usize,
+    },
+    /// A string or key exceeded [`JsonLimits::max_string_len`].
+    StringTooLong {
+        /// The configured limit.
+        limit: usize,
+    },
+    /// A number exceeded [`JsonLimits::max_number_len`].
+    NumberTooLong {
+        /// The configured limit.
+        limit: usize,
+    },
+    /// The document opened more than [`JsonLimits::max_containers`] objects
+    /// and arrays.
+    ContainerLimitExceeded {
+        /// The configured limit.
+        limit: u64,
+    },
+    /// A captured value exceeded [`JsonLimits::max_value_len`].
+    ValueTooLong {
+        /// The configured limit.
+        limit: usize,
+    },
+    /// A path expression could not be parsed.
+    InvalidPath(String),
+    /// The writer was driven out of JSON order, e.g. a value without a key
+    /// inside an object.
+    WriterState(&'static str),
+    /// serde failed to convert a value.
+    Serde(serde_json::Error),
+    /// I/O failed on the underlying reader or writer.
+    Io(io::Error),
+}
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { offset, reason } => write!(f, "{reason} at byte {offset}"),
+            Self::UnexpectedEof { offset } => write!(f, "unexpected end of input at byte {offset}"),
+            Self::DepthLimitExceeded { limit } => write!(f, "nesting deeper than {limit}"),
+            Self::StringTooLong { limit } => write!(f, "string longer than {limit} bytes"),
+            Self::NumberTooLong { limit } => write!(f, "number longer than {limit} bytes"),
+            Self::ContainerLimitExceeded { limit } => {
+                write!(f, "more than {limit} objects and arrays")
+            }
+            Self::ValueTooLong { limit } => write!(f, "value longer than {limit} bytes"),
+            Self::InvalidPath(path) => write!(f, "invalid JSON path `{path}`"),
+            Self::WriterState(reason) => write!(f, "invalid JSON writer call: {reason}"),
+            Self::Serde(err) => write!(f, "serde error: {err}"),
+            Self::Io(err) => write!(f, "i/o error during JSON streaming: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Serde(err) => Some(err),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for JsonError {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    #[inline]
+    fn from(err: serde_json::Error) -> Self {
+        Self::Serde(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Documents the parser must accept, after JSONTestSuite's `y_` cases.
+    pub(super) const ACCEPT: &[&[u8]] = &[
+        b"[[]   ]",
+        b"[\"\"]",
+        b"[]",
+        b"[\"a\"]",
+        b"[false]",
+        b"[null, 1, \"1\", {}]",
+        b"[null]",
+        b"[1\n]",
+        b" [1]",
+        b"[1,null,null,null,2]",
+        b"[2] ",
+        b"[123e65]",
+        b"[0e+1]",
+        b"[0e1]",
+        b"[ 4]",
+        b"[-0.000000000000000000000000000000000000000000000000000000000000000001]\n",
+        b"[20e1]",
+        b"[-0]",
+        b"[-123]",
+        b"[1E22]",
+        b"[1E-2]",
+        b"[1E+2]",
+        b"[123.456e78]",
+        b"[1e-2]",
+        b"[123.456789]",
+        b"{\"asd\":\"sdf\", \"dfg\":\"fgh\"}",
+        b"{\"a\":\"b\",\"a\":\"c\"}",
+        b"{}",
+        b"{\"\":0}",
+        b"{\"foo\\u0000bar\": 42}",
+        b"{ \"min\": -1.0e+28, \"max\": 1.0e+28 }",
+        b"{\"x\":[{\"id\": \"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx\"}], \"id\": \"xxx\"}",
+        b"{\"a\":[]}",
+        b"[\"\\u0060\\u012a\\u12AB\"]",
+        b"[\"\\uD801\\udc37\"]",
+        b"[\"\\ud83d\\ude39\\ud83d\\udc8d\"]",
+        b"[\"\\\"\\\\\\/\\b\\f\\n\\r\\t\"]",
+        b"[\"\\\\u0000\"]",
+        b"[\"a/*b*/c/*d//e\"]",
+        b"[\"\\uFFFF\"]",
+        b"[\"\\uDBFF\\uDFFF\"]",
+        b"[\"new\\u00A0line\"]",
+        "[\"π\", \"€𝄞\"]".as_bytes(),
+        b"\" \"",
+        b"false",
+        b"42",
+        b"-0.1",
+        b"null",
+        b"true",
+        b"\"\"",
+        b" [] ",
+        b"\t\r\n{\"a\" : [ true , {\"b\" : null } ] }\n",
+    ];
+
+    /// Documents the parser must reject, after JSONTestSuite's `n_` cases.
+    pub(super) const REJECT: &[&[u8]] = &[
+        b"",
+        b" ",
+        b"[1 true]",
+        b"[a\xe5]",
+        b"[\"\": 1]",
+        b"[\"\"],",
+        b"[,1]",
+        b"[1,,2]",
+        b"[\"x\",,]",
+        b"[\"x\"]]",
+        b"[\"\",]",
+        b"[\"x\"",
+        b"[x",
+        b"[3[4]]",
+        b"[\xff]",
+        b"[1:2]",
+        b"[,]",
+        b"[-]",
+        b"[1,]",
+        b"[*]",
+        b"[1,",
+        b"[{}",
+        b"[\"a\" \"b\"]",
+        b"[tru]",
+        b"[True]",
+        b"[++1234]",
+        b"[+1]",
+        b"[-01]",
+        b"[-1.0.]",
+        b"[-2.]",
+        b"[.-1]",
+        b"[.2e-3]",
+        b"[0.3e+]",
+        b"[0.3e]",
+        b"[0.e1]",
+        b"[0E]",
+        b"[1eE2]",
+        b"[2.e3]",
+        b"[NaN]",
+        b"[Infinity]",
+        b"[012]",
+        b"[0x42]",
+        b"[1.2a-3]",
+        b"[- 1]",
+        b"[-.123]",
+        b"{\"a\":\"a\" 123}",
+        b"{key: 'value'}",
+        b"{\"a\" b}",
+        b"{:\"b\"}",
+        b"{\"a\" \"b\"}",
+        b"{\"a\":",
+        b"{\"a\"",
+        b"{1:1}",
+        b"{null:null}",
+        b"{\"id\":0,}",
+        b"{'a':0}",
+        b"{\"a\":\"b\"}/**/",
+        b"{\"a\":\"b\",,\"c\":\"d\"}",
+        b"{\"a\":\"a",
+        b"{ \"foo\" : \"bar\", \"a\" }",
+        b"{\"a\":\"b\"}#",
+        b"{\"a\":\"b\"}}",
+        b"{\"x\"::\"b\"}",
+        b"{\"a\":1 \"b\":2}",
+        b"[\"\\uD800\\\"]",
+        b"[\"\\uD800\\u\"]",
+        b"[\"\\u00A\"]",
+        b"[\"\\a\"]",
+        b"[\"\\uqqqq\"]",
+        b"[\"\\uD800\"]",
+        b"[\"\\uDC00\"]",
+        b"[\"\\uD800\\uD800\"]",
+        b"[\"\x00\"]",
+        b"[\"\t\"]",
+        b"[\"new\nline\"]",
+        b"[\"\xff\"]",
+        b"['single quote']",
+        b"abc",
+        b"[\"\\",
+        b"\"",
+        b"nul",
+        b"fals",
+        b"]",
+        b"}",
+        b"[]]",
+        b"[1]x",
+        b"[][]",
+        b"{\"a\":true} \"x\"",
+        b"[1]\x00",
+        b"\xef\xbb\xbf{}",
+        "[é]".as_bytes(),
+    ];
+
+    fn parse_all(input: &[u8]) -> Result<usize, JsonError> {
+        let mut parser = JsonSliceParser::new(input);
+        let mut tokens = 0;
+        while parser.next_token()?.is_some() {
+            tokens += 1;
+        }
+        Ok(tokens)
+    }
+
+    #[test]
+    fn conformance_accepts_valid_documents() {
+        init_test("conformance_accepts_valid_documents");
+        for doc in ACCEPT {
+            let result = parse_all(doc);
+            crate::assert_with_log!(
+                result.is_ok(),
+                String::from_utf8_lossy(doc),
+                "accepted",
+                format!("{result:?}")
+            );
+        }
+        crate::test_complete!("conformance_accepts_valid_documents");
+    }
+
+    #[test]
+    fn conformance_rejects_invalid_documents() {
+        init_test("conformance_rejects_invalid_documents");
+        for doc in REJECT {
+            let result = parse_all(doc);
+            crate::assert_with_log!(
+                result.is_err(),
+                String::from_utf8_lossy(doc),
+                "rejected",
+                format!("{result:?}")
+            );
+        }
+        crate::test_complete!("conformance_rejects_invalid_documents");
+    }
+
+    #[test]
+    fn accepted_documents_agree_with_serde_json() {
+        init_test("accepted_documents_agree_with_serde_json");
+        for doc in ACCEPT.iter().chain(REJECT) {
+            let ours = parse_all(doc).is_ok();
+            let serde = serde_json::from_slice::<serde_json::Value>(doc).is_ok();
+            crate::assert_with_log!(ours == serde, String::from_utf8_lossy(doc), serde, ours);
+        }
+        crate::test_complete!("accepted_documents_agree_with_serde_json");
+    }
+
+    #[test]
+    fn tokens_match_document_structure() {
+        init_test("tokens_match_document_structure");
+        let mut parser =
+            JsonSliceParser::new(br#"{"a":[1,-2.5e3,true,null],"b\n":"x\u00e9"}"#);
+        let mut tokens = Vec::new();
+        while let Some(token) = parser.next_token().expect("valid document") {
+            tokens.push(format!("{token:?}"));
+        }
+        let expected = [
+            "BeginObject",
+            "Key(\"a\")",
+            "BeginArray",
+            "Number(\"1\")",
+            "Number(\"-2.5e3\")",
+            "Bool(true)",
+            "Null",
+            "EndArray",
+            "Key(\"b\\n\")",
+            "String(\"xé\")",
+            "EndObject",
+        ];
+        crate::assert_with_log!(tokens == expected, "token stream", expected, tokens);
+        crate::test_complete!("tokens_match_document_structure");
+    }
+
+    #[test]
+    fn limits_reject_deep_nesting_and_long_tokens() {
+        init_test("limits_reject_deep_nesting_and_long_tokens");
+        let deep = "[".repeat(DEFAULT_MAX_DEPTH + 1);
+        let result = parse_all(deep.as_bytes());
+        let depth_limited = matches!(result, Err(JsonError::DepthLimitExceeded { limit: 128 }));
+        crate::assert_with_log!(depth_limited, "depth", "DepthLimitExceeded", result);
+
+        let limits = JsonLimits::new()
+            .with_max_string_len(8)
+            .with_max_number_len(4)
+            .with_max_containers(2);
+        let cases: [(&[u8], fn(&JsonError) -> bool); 3] = [
+            (b"[\"123456789\"]", |e| matches!(e, JsonError::StringTooLong { limit: 8 })),
+            (b"[12345]", |e| matches!(e, JsonError::NumberTooLong { limit: 4 })),
+            (b"[[],[]]", |e| matches!(e, JsonError::ContainerLimitExceeded { limit: 2 })),
+        ];
+        for (doc, expected) in cases {
+            let mut parser = JsonSliceParser::with_limits(doc, limits);
+            let err = loop {
+                match parser.next_token() {
+                    Ok(Some(_)) => {}
+                    Ok(None) => panic!("document should exceed a limit"),
+                    Err(err) => break err,
+                }
+            };
+            crate::assert_with_log!(expected(&err), String::from_utf8_lossy(doc), true, err);
+        }
+        crate::test_complete!("limits_reject_deep_nesting_and_long_tokens");
+    }
+}
@@ -0,0 +1,437 @@
+//! Path selection over a streaming [`JsonReader`].
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use super::JsonError;
+use super::reader::JsonReader;
+use super::scanner::Kind;
+use crate::io::AsyncRead;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(u64),
+    Wildcard,
+}
+
+/// A JSONPath subset for streaming selection.
+///
+/// Supported syntax is the root `$` followed by any of `.key`, `["key"]`,
+/// `['key']`, `[N]`, `.*` and `[*]`, e.g. `$.items[*]` or
+/// `$.pages[0]["entries"][*].id`. Each segment matches exactly one level, so
+/// selected values never overlap and can be deserialized as soon as they
+/// close.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// Parses a path expression.
+    pub fn parse(path: &str) -> Result<Self, JsonError> {
+        let invalid = || JsonError::InvalidPath(path.to_string());
+        let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+        while let Some(c) = rest.chars().next() {
+            let (segment, len) = match c {
+                '.' => {
+                    let name = &rest[1..];
+                    let end = name.find(['.', '[']).unwrap_or(name.len());
+                    match &name[..end] {
+                        "" => return Err(invalid()),
+                        "*" => (Segment::Wildcard, 1 + end),
+                        key => (Segment::Key(key.to_string()), 1 + end),
+                    }
+                }
+                '[' => {
+                    let end = rest.find(']').ok_or_else(invalid)?;
+                    let inner = rest[1..end].trim();
+                    let segment = if inner == "*" {
+                        Segment::Wildcard
+                    } else if let Some(key) = quoted(inner) {
+                        Segment::Key(key.to_string())
+                    } else {
+                        Segment::Index(inner.parse().map_err(|_| invalid())?)
+                    };
+                    (segment, end + 1)
+                }
+                _ => return Err(invalid()),
+            };
+            segments.push(segment);
+            rest = &rest[len..];
+        }
+        Ok(Self {
+            source: path.to_string(),
+            segments,
+        })
+    }
+}
+
+fn quoted(s: &str) -> Option<&str> {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)))
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Position of the selection within one open container.
+#[derive(Debug)]
+enum Frame {
+    Object { key: String },
+    Array { index: u64 },
+}
+
+impl Frame {
+    fn matches(&self, segment: &Segment) -> bool {
+        match (self, segment) {
+            (_, Segment::Wildcard) => true,
+            (Self::Object { key }, Segment::Key(want)) => key == want,
+            (Self::Array { index }, Segment::Index(want)) => index == want,
+            _ => false,
+        }
+    }
+}
+
+/// Values selected from a [`JsonReader`] by a [`JsonPath`].
+///
+/// Created by [`JsonReader::select`]. Subtrees that cannot contain a match
+/// are skipped without being buffered; each match is captured (up to
+/// [`JsonLimits::max_value_len`](super::JsonLimits::max_value_len)) and
+/// handed to serde when it closes.
+#[derive(Debug)]
+pub struct Selection<'r, R> {
+    reader: &'r mut JsonReader<R>,
+    path: JsonPath,
+    frames: Vec<Frame>,
+}
+
+impl<'r, R> Selection<'r, R> {
+    pub(super) const fn new(reader: &'r mut JsonReader<R>, path: JsonPath) -> Self {
+        Self {
+            reader,
+            path,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns the path being selected.
+    #[must_use]
+    pub const fn path(&self) -> &JsonPath {
+        &self.path
+    }
+
+    /// Whether the value about to start sits at the selected path, or at an
+    /// ancestor of it.
+    fn position(&self) -> (bool, bool) {
+        let segments = &self.path.segments;
+        let on_path = self
+            .frames
+            .iter()
+            .zip(segments)
+            .all(|(frame, segment)| frame.matches(segment));
+        let depth = self.frames.len();
+        (on_path && depth == segments.len(), on_path && depth < segments.len())
+    }
+
+    /// Marks the current array element, if any, as complete.
+    fn advance(&mut self) {
+        if let Some(Frame::Array { index }) = self.frames.last_mut() {
+            *index += 1;
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Selection<'_, R> {
+    /// Returns the next selected value, or `None` once the document is
+    /// complete.
+    pub async fn next<T: DeserializeOwned>(&mut self) -> Result<Option<T>, JsonError> {
+        loop {
+            let Some(scanned) = self.reader.next_scanned().await? else {
+                return Ok(None);
+            };
+            match scanned.kind {
+                Kind::Key => {
+                    let key = self.reader.key(scanned);
+                    if let Some(Frame::Object { key: current }) = self.frames.last_mut() {
+                        current.clear();
+                        current.push_str(key);
+                    }
+                }
+                Kind::EndObject | Kind::EndArray => {
+                    self.frames.pop();
+                    self.advance();
+                }
+                kind => {
+                    let (selected, ancestor) = self.position();
+                    if selected {
+                        let range = self.reader.value_range(scanned).await?;
+                        let value = self.reader.deserialize(range)?;
+                        self.advance();
+                        return Ok(Some(value));
+                    }
+                    let frame = match kind {
+                        Kind::BeginObject if ancestor => Frame::Object { key: String::new() },
+                        Kind::BeginArray if ancestor => Frame::Array { index: 0 },
+                        _ => {
+                            self.reader.skip_value(scanned).await?;
+                            self.advance();
+                            continue;
+                        }
+                    };
+                    self.frames.push(frame);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::codec::json::reader::tests::Chunked;
+    use crate::io::ReadBuf;
+    use serde::Deserialize;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn select<T: DeserializeOwned>(doc: &str, path: &str) -> Result<Vec<T>, JsonError> {
+        futures_lite::future::block_on(async {
+            let path = JsonPath::parse(path)?;
+            let mut reader = JsonReader::new(Chunked::new(doc.as_bytes(), &[5]));
+            let mut selection = reader.select(&path);
+            let mut values = Vec::new();
+            while let Some(value) = selection.next().await? {
+                values.push(value);
+            }
+            Ok(values)
+        })
+    }
+
+    #[test]
+    fn path_parsing() {
+        init_test("path_parsing");
+        let path = JsonPath::parse("$.pages[0][\"entries\"][*].id").expect("valid path");
+        let expected = vec![
+            Segment::Key("pages".into()),
+            Segment::Index(0),
+            Segment::Key("entries".into()),
+            Segment::Wildcard,
+            Segment::Key("id".into()),
+        ];
+        crate::assert_with_log!(path.segments == expected, "segments", expected, path.segments);
+        let display = path.to_string();
+        crate::assert_with_log!(display.starts_with("$.pages[0]"), "display", "source", display);
+        for bad in ["", "items", "$.", "$[", "$[x]", "$..a"] {
+            let result = JsonPath::parse(bad);
+            crate::assert_with_log!(result.is_err(), bad, "InvalidPath", result);
+        }
+        crate::test_complete!("path_parsing");
+    }
+
+    #[test]
+    fn selects_items_and_skips_unrelated_subtrees() {
+        init_test("selects_items_and_skips_unrelated_subtrees");
+        let doc = r#"{"meta":{"items":[9]},"items":[{"id":1},{"id":2,"x":[1]}],"tail":[]}"#;
+        let ids: Vec<u64> = select::<serde_json::Value>(doc, "$.items[*]")
+            .expect("valid selection")
+            .iter()
+            .map(|item| item["id"].as_u64().expect("numeric id"))
+            .collect();
+        crate::assert_with_log!(ids == [1, 2], "item ids", [1, 2], ids);
+
+        let second: Vec<u64> = select(doc, "$.items[1].x[0]").expect("valid selection");
+        crate::assert_with_log!(second == [1], "indexed", [1], second);
+
+        let nested: Vec<u64> = select(r#"[[1,2],[3],[]]"#, "$[*][*]").expect("valid selection");
+        crate::assert_with_log!(nested == [1, 2, 3], "nested", [1, 2, 3], nested);
+
+        let root: Vec<bool> = select("true", "$").expect("valid selection");
+        crate::assert_with_log!(root == [true], "root", [true], root);
+
+        let escaped_key: Vec<u64> =
+            select(r#"{"items":[7]}"#, "$['items'][*]").expect("valid selection");
+        crate::assert_with_log!(escaped_key == [7], "escaped key", [7], escaped_key);
+        crate::test_complete!("selects_items_and_skips_unrelated_subtrees");
+    }
+
+    #[test]
+    fn selection_reports_syntax_errors_in_skipped_values() {
+        init_test("selection_reports_syntax_errors_in_skipped_values");
+        let result = select::<u64>(r#"{"skip":[1,,2],"items":[1]}"#, "$.items[*]");
+        let syntax = matches!(result, Err(JsonError::Syntax { .. }));
+        crate::assert_with_log!(syntax, "syntax error", "Syntax", result);
+        crate::test_complete!("selection_reports_syntax_errors_in_skipped_values");
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        id: u64,
+        name: String,
+        payload: String,
+    }
+
+    /// Generates `{"meta":{...},"items":[...]}` with `count` items of
+    /// roughly `payload` bytes each, without materializing the document.
+    struct Generated {
+        count: u64,
+        payload: usize,
+        next: u64,
+        pending: Vec<u8>,
+        offset: usize,
+        finished: bool,
+    }
+
+    impl Generated {
+        fn new(count: u64, payload: usize) -> Self {
+            Self {
+                count,
+                payload,
+                next: 0,
+                pending: br#"{"meta":{"version":1,"tags":["a","b"]},"items":["#.to_vec(),
+                offset: 0,
+                finished: false,
+            }
+        }
+
+        fn refill(&mut self) {
+            self.pending.clear();
+            self.offset = 0;
+            if self.next == self.count {
+                self.pending.extend_from_slice(b"]}");
+                self.finished = true;
+                return;
+            }
+            if self.next > 0 {
+                self.pending.push(b',');
+            }
+            let id = self.next;
+            self.pending.extend_from_slice(
+                format!(r#"{{"id":{id},"name":"item-{id}","tags":["x"],"payload":""#).as_bytes(),
+            );
+            self.pending.resize(self.pending.len() + self.payload, b'p');
+            self.pending.extend_from_slice(br#""}"#);
+            self.next += 1;
+        }
+    }
+
+    impl AsyncRead for Generated {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            while buf.remaining() > 0 {
+                if self.offset == self.pending.len() {
+                    if self.finished {
+                        break;
+                    }
+                    self.refill();
+                }
+                let offset = self.offset;
+                let n = buf.remaining().min(self.pending.len() - offset);
+                buf.put_slice(&self.pending[offset..offset + n]);
+                self.offset += n;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Streams `count` generated items through `$.items[*]`, checking each
+    /// one and returning the largest buffer capacity seen.
+    fn extract_generated(count: u64, payload: usize) -> usize {
+        futures_lite::future::block_on(async {
+            let path = JsonPath::parse("$.items[*]").expect("valid path");
+            let mut reader = JsonReader::new(Generated::new(count, payload));
+            let mut selection = reader.select(&path);
+            let mut seen = 0;
+            let mut max_capacity = 0;
+            while let Some(item) = selection.next::<Item>().await.expect("valid item") {
+                assert_eq!(item.id, seen);
+                assert_eq!(item.name, format!("item-{seen}"));
+                assert_eq!(item.payload.len(), payload);
+                seen += 1;
+                max_capacity = max_capacity.max(selection.reader.buffer_capacity());
+            }
+            assert_eq!(seen, count);
+            max_capacity
+        })
+    }
+
+    #[test]
+    fn extraction_from_large_document_uses_bounded_buffer() {
+        init_test("extraction_from_large_document_uses_bounded_buffer");
+        // 16 Ki items of ~1 KiB: a 16 MiB document.
+        let max_capacity = extract_generated(16 * 1024, 1000);
+        crate::assert_with_log!(
+            max_capacity <= 64 * 1024,
+            "read buffer bounded",
+            "<= 64 KiB",
+            max_capacity
+        );
+        crate::test_complete!("extraction_from_large_document_uses_bounded_buffer");
+    }
+
+    #[cfg(target_os = "linux")]
+    fn resident_set_kib() -> u64 {
+        std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("VmRSS:"))
+                    .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse().ok())
+            })
+            .unwrap_or(0)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "Streams a 1 GiB document; run with --ignored"]
+    fn extraction_from_gigabyte_document_has_bounded_rss() {
+        init_test("extraction_from_gigabyte_document_has_bounded_rss");
+        let before = resident_set_kib();
+        // 1 Mi items of ~1 KiB.
+        let max_capacity = extract_generated(1024 * 1024, 1000);
+        let growth = resident_set_kib().saturating_sub(before);
+        crate::assert_with_log!(
+            max_capacity <= 64 * 1024,
+            "read buffer bounded",
+            "<= 64 KiB",
+            max_capacity
+        );
+        crate::assert_with_log!(growth < 64 * 1024, "RSS growth (KiB)", "< 64 MiB", growth);
+        crate::test_complete!("extraction_from_gigabyte_document_has_bounded_rss");
+    }
+}
@@ -0,0 +1,472 @@
+//! Pull parsers over slices and async readers.
+
+use std::ops::Range;
+
+use serde::de::DeserializeOwned;
+
+use super::path::{JsonPath, Selection};
+use super::scanner::{self, Kind, Scan, Scanned, Scanner};
+use super::{JsonError, JsonLimits, Token};
+use crate::io::{AsyncRead, AsyncReadExt};
+
+/// Bytes requested from the underlying reader per read by default.
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Builds the public token for `scanned`, decoding escapes into `scratch`.
+fn token<'a>(buf: &'a [u8], scanned: Scanned, scratch: &'a mut String) -> Token<'a> {
+    // The scanner has validated UTF-8 for strings, and numbers are ASCII.
+    let text = std::str::from_utf8(&buf[scanned.contents()]).unwrap_or_default();
+    let text = if scanned.escaped {
+        scanner::unescape(text, scratch);
+        scratch.as_str()
+    } else {
+        text
+    };
+    match scanned.kind {
+        Kind::BeginObject => Token::BeginObject,
+        Kind::EndObject => Token::EndObject,
+        Kind::BeginArray => Token::BeginArray,
+        Kind::EndArray => Token::EndArray,
+        Kind::Key => Token::Key(text),
+        Kind::String => Token::String(text),
+        Kind::Number => Token::Number(text),
+        Kind::True => Token::Bool(true),
+        Kind::False => Token::Bool(false),
+        Kind::Null => Token::Null,
+    }
+}
+
+/// Pull parser over an in-memory document.
+///
+/// Strings without escapes borrow from the input slice. After an error the
+/// parser's position is unspecified and it should be dropped.
+#[derive(Debug)]
+pub struct JsonSliceParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    scanner: Scanner,
+    scratch: String,
+}
+
+impl<'a> JsonSliceParser<'a> {
+    /// Creates a parser over `input` with the default [`JsonLimits`].
+    #[must_use]
+    pub fn new(input: &'a [u8]) -> Self {
+        Self::with_limits(input, JsonLimits::new())
+    }
+
+    /// Creates a parser over `input` with custom limits.
+    #[must_use]
+    pub fn with_limits(input: &'a [u8], limits: JsonLimits) -> Self {
+        Self {
+            input,
+            pos: 0,
+            scanner: Scanner::new(limits),
+            scratch: String::new(),
+        }
+    }
+
+    /// Returns the next token, or `None` once the document is complete.
+    pub fn next_token(&mut self) -> Result<Option<Token<'_>>, JsonError> {
+        match self.scanner.scan(self.input, &mut self.pos, true)? {
+            Scan::Token(scanned) => Ok(Some(token(self.input, scanned, &mut self.scratch))),
+            Scan::End | Scan::NeedMore => Ok(None),
+        }
+    }
+}
+
+/// Pull parser over an [`AsyncRead`].
+///
+/// The reader holds at most the token being scanned (or the value being
+/// captured for serde) plus one read chunk, so memory stays bounded by
+/// [`JsonLimits`] however large the document is. After an error the
+/// reader's position is unspecified and it should be dropped.
+#[derive(Debug)]
+pub struct JsonReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    pos: usize,
+    eof: bool,
+    chunk_size: usize,
+    scanner: Scanner,
+    scratch: String,
+    /// Start of a value being captured; bytes from here on are retained.
+    capture: Option<usize>,
+}
+
+impl<R> JsonReader<R> {
+    /// Creates a reader with the default [`JsonLimits`].
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self::with_limits(reader, JsonLimits::new())
+    }
+
+    /// Creates a reader with custom limits.
+    #[must_use]
+    pub fn with_limits(reader: R, limits: JsonLimits) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            pos: 0,
+            eof: false,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            scanner: Scanner::new(limits),
+            scratch: String::new(),
+            capture: None,
+        }
+    }
+
+    /// Sets how many bytes are requested from the underlying reader at a
+    /// time (default 8 KiB).
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Returns the capacity of the internal read buffer, for monitoring
+    /// memory use.
+    #[must_use]
+    pub fn buffer_capacity(&self) -> usize {
+        self.buf.capacity() + self.scratch.capacity()
+    }
+
+    /// Returns the underlying reader, discarding any buffered input.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Selects the values matching `path`, which is evaluated from the
+    /// document root. Call this before reading any tokens.
+    pub fn select(&mut self, path: &JsonPath) -> Selection<'_, R> {
+        Selection::new(self, path.clone())
+    }
+}
+
+impl<R: AsyncRead + Unpin> JsonReader<R> {
+    /// Returns the next token, or `None` once the document is complete.
+    pub async fn next_token(&mut self) -> Result<Option<Token<'_>>, JsonError> {
+        let Some(scanned) = self.next_scanned().await? else {
+            return Ok(None);
+        };
+        Ok(Some(token(&self.buf, scanned, &mut self.scratch)))
+    }
+
+    /// Reads the next complete value and deserializes it.
+    ///
+    /// The reader must be positioned where a value may start: at the
+    /// document root, inside an array, or after a key. Returns `None` if the
+    /// enclosing array or object closes instead (the closing token is
+    /// consumed) or the document is complete.
+    pub async fn read_value<T: DeserializeOwned>(&mut self) -> Result<Option<T>, JsonError> {
+        let Some(first) = self.next_scanned().await? else {
+            return Ok(None);
+        };
+        match first.kind {
+            Kind::EndObject | Kind::EndArray => Ok(None),
+            Kind::Key => Err(JsonError::Syntax {
+                offset: self.scanner.offset(first.start),
+                reason: "expected a value, found an object key",
+            }),
+            _ => {
+                let range = self.value_range(first).await?;
+                self.deserialize(range).map(Some)
+            }
+        }
+    }
+
+    pub(super) async fn next_scanned(&mut self) -> Result<Option<Scanned>, JsonError> {
+        loop {
+            match self.scanner.scan(&self.buf, &mut self.pos, self.eof)? {
+                Scan::Token(scanned) => return Ok(Some(scanned)),
+                Scan::End => return Ok(None),
+                Scan::NeedMore => self.fill().await?,
+            }
+        }
+    }
+
+    /// Decodes a key token returned by [`Self::next_scanned`].
+    pub(super) fn key(&mut self, scanned: Scanned) -> &str {
+        match token(&self.buf, scanned, &mut self.scratch) {
+            Token::Key(key) => key,
+            _ => "",
+        }
+    }
+
+    /// Finishes the value that starts with `first` and returns its raw byte
+    /// range, which stays valid until the next read.
+    pub(super) async fn value_range(&mut self, first: Scanned) -> Result<Range<usize>, JsonError> {
+        if !matches!(first.kind, Kind::BeginObject | Kind::BeginArray) {
+            return Ok(first.start..first.end);
+        }
+        self.capture = Some(first.start);
+        let end = self.finish_container().await;
+        let start = self.capture.take().unwrap_or_default();
+        Ok(start..end?)
+    }
+
+    /// Skips the rest of the value that starts with `first`.
+    pub(super) async fn skip_value(&mut self, first: Scanned) -> Result<(), JsonError> {
+        if matches!(first.kind, Kind::BeginObject | Kind::BeginArray) {
+            self.finish_container().await?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn deserialize<T: DeserializeOwned>(
+        &self,
+        range: Range<usize>,
+    ) -> Result<T, JsonError> {
+        Ok(serde_json::from_slice(&self.buf[range])?)
+    }
+
+    /// Reads up to the end of the container just opened, returning the end
+    /// offset of its closing bracket.
+    async fn finish_container(&mut self) -> Result<usize, JsonError> {
+        let mut depth = 1_usize;
+        loop {
+            let Some(scanned) = self.next_scanned().await? else {
+                return Err(JsonError::UnexpectedEof {
+                    offset: self.scanner.offset(self.buf.len()),
+                });
+            };
+            match scanned.kind {
+                Kind::BeginObject | Kind::BeginArray => depth += 1,
+                Kind::EndObject | Kind::EndArray => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(scanned.end);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Drops consumed input and reads one more chunk.
+    async fn fill(&mut self) -> Result<(), JsonError> {
+        if let Some(capture) = self.capture {
+            let limit = self.scanner.limits().max_value_len;
+            if self.buf.len() - capture > limit {
+                return Err(JsonError::ValueTooLong { limit });
+            }
+        }
+        let keep = self.capture.unwrap_or(self.pos);
+        if keep > 0 {
+            self.buf.drain(..keep);
+            self.pos -= keep;
+            self.capture = self.capture.map(|capture| capture - keep);
+            self.scanner.discard(keep);
+        }
+
+        let filled = self.buf.len();
+        self.buf.resize(filled + self.chunk_size, 0);
+        let read = self.reader.read(&mut self.buf[filled..]).await;
+        self.buf.truncate(filled + *read.as_ref().unwrap_or(&0));
+        if read? == 0 {
+            self.eof = true;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::io::ReadBuf;
+    use proptest::prelude::*;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Serves `data` in reads of the given sizes, cycling through them.
+    pub(in crate::codec::json) struct Chunked {
+        data: Vec<u8>,
+        pos: usize,
+        sizes: Vec<usize>,
+        next: usize,
+    }
+
+    impl Chunked {
+        pub(in crate::codec::json) fn new(data: &[u8], sizes: &[usize]) -> Self {
+            Self {
+                data: data.to_vec(),
+                pos: 0,
+                sizes: sizes.to_vec(),
+                next: 0,
+            }
+        }
+    }
+
+    impl AsyncRead for Chunked {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let size = self.sizes[self.next % self.sizes.len()].max(1);
+            self.next += 1;
+            let end = (self.pos + size.min(buf.remaining())).min(self.data.len());
+            buf.put_slice(&self.data[self.pos..end]);
+            self.pos = end;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn slice_tokens(doc: &[u8]) -> Result<Vec<String>, String> {
+        let mut parser = JsonSliceParser::new(doc);
+        let mut tokens = Vec::new();
+        loop {
+            match parser.next_token() {
+                Ok(Some(token)) => tokens.push(format!("{token:?}")),
+                Ok(None) => return Ok(tokens),
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+    }
+
+    fn stream_tokens(doc: &[u8], sizes: &[usize]) -> Result<Vec<String>, String> {
+        futures_lite::future::block_on(async {
+            let mut reader = JsonReader::new(Chunked::new(doc, sizes));
+            let mut tokens = Vec::new();
+            loop {
+                match reader.next_token().await {
+                    Ok(Some(token)) => tokens.push(format!("{token:?}")),
+                    Ok(None) => return Ok(tokens),
+                    Err(err) => return Err(err.to_string()),
+                }
+            }
+        })
+    }
+
+    const SPLIT_HEAVY: &[u8] = br#"{"kéy":["😹 long string with \"escapes\"",
+        12345.678e-9,true,false,null,{"nested":[[],{}],"n":-0.5E+10}]}"#;
+
+    fn corpus() -> Vec<&'static [u8]> {
+        let mut corpus = vec![SPLIT_HEAVY];
+        corpus.extend_from_slice(crate::codec::json::tests::ACCEPT);
+        corpus.extend_from_slice(crate::codec::json::tests::REJECT);
+        corpus
+    }
+
+    #[test]
+    fn unescaped_strings_borrow_from_input() {
+        init_test("unescaped_strings_borrow_from_input");
+        let input = br#"{"plain":"borrowed","esc":"a\nb"}"#;
+        let range = input.as_ptr_range();
+        let mut parser = JsonSliceParser::new(input);
+        let mut borrowed = Vec::new();
+        while let Some(token) = parser.next_token().expect("valid document") {
+            if let Token::Key(text) | Token::String(text) = token {
+                borrowed.push((text.to_string(), range.contains(&text.as_ptr())));
+            }
+        }
+        let expected = vec![
+            ("plain".to_string(), true),
+            ("borrowed".to_string(), true),
+            ("esc".to_string(), true),
+            ("a\nb".to_string(), false),
+        ];
+        crate::assert_with_log!(borrowed == expected, "zero-copy strings", expected, borrowed);
+        crate::test_complete!("unescaped_strings_borrow_from_input");
+    }
+
+    #[test]
+    fn chunked_stream_matches_slice_parse_for_corpus() {
+        init_test("chunked_stream_matches_slice_parse_for_corpus");
+        for doc in corpus() {
+            let expected = slice_tokens(doc);
+            let actual = stream_tokens(doc, &[1]);
+            crate::assert_with_log!(
+                actual == expected,
+                String::from_utf8_lossy(doc),
+                expected,
+                actual
+            );
+        }
+        crate::test_complete!("chunked_stream_matches_slice_parse_for_corpus");
+    }
+
+    proptest! {
+        #[test]
+        fn chunk_boundaries_do_not_change_tokens(
+            index in 0..corpus().len(),
+            sizes in proptest::collection::vec(1_usize..24, 1..8),
+        ) {
+            let doc = corpus()[index];
+            prop_assert_eq!(stream_tokens(doc, &sizes), slice_tokens(doc));
+        }
+    }
+
+    #[test]
+    fn oversized_string_fails_before_buffering_it() {
+        init_test("oversized_string_fails_before_buffering_it");
+        let mut doc = b"[\"".to_vec();
+        doc.resize(8 * 1024 * 1024, b'x');
+        futures_lite::future::block_on(async {
+            let limits = JsonLimits::new().with_max_string_len(1024);
+            let mut reader = JsonReader::with_limits(Chunked::new(&doc, &[4096]), limits)
+                .with_chunk_size(4096);
+            let first = reader.next_token().await.expect("array opens");
+            crate::assert_with_log!(
+                first == Some(Token::BeginArray),
+                "first token",
+                Some(Token::BeginArray),
+                first
+            );
+            let err = reader.next_token().await.expect_err("string is too long");
+            let too_long = matches!(err, JsonError::StringTooLong { limit: 1024 });
+            crate::assert_with_log!(too_long, "string limit", "StringTooLong", err);
+            let capacity = reader.buffer_capacity();
+            crate::assert_with_log!(capacity <= 16 * 1024, "buffer bounded", "<= 16 KiB", capacity);
+        });
+        crate::test_complete!("oversized_string_fails_before_buffering_it");
+    }
+
+    #[test]
+    fn read_value_deserializes_and_enforces_value_limit() {
+        init_test("read_value_deserializes_and_enforces_value_limit");
+        futures_lite::future::block_on(async {
+            let doc = br#"[{"id":1,"tags":["a"]},{"id":2,"tags":[]}]"#;
+            let mut reader = JsonReader::new(Chunked::new(doc, &[3]));
+            reader.next_token().await.expect("array opens");
+            let mut ids = Vec::new();
+            while let Some(value) = reader
+                .read_value::<serde_json::Value>()
+                .await
+                .expect("valid value")
+            {
+                ids.push(value["id"].as_u64().expect("numeric id"));
+            }
+            crate::assert_with_log!(ids == [1, 2], "ids", [1, 2], ids);
+            let end = reader.next_token().await.expect("document complete");
+            crate::assert_with_log!(end.is_none(), "end of document", "None", end);
+
+            let big = format!("[{}]", "1,".repeat(1024) + "1");
+            let limits = JsonLimits::new().with_max_value_len(256);
+            let mut reader = JsonReader::with_limits(Chunked::new(big.as_bytes(), &[64]), limits)
+                .with_chunk_size(64);
+            let err = reader
+                .read_value::<Vec<u32>>()
+                .await
+                .expect_err("value exceeds limit");
+            let too_long = matches!(err, JsonError::ValueTooLong { limit: 256 });
+            crate::assert_with_log!(too_long, "value limit", "ValueTooLong", err);
+        });
+        crate::test_complete!("read_value_deserializes_and_enforces_value_limit");
+    }
+}
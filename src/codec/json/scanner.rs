@@ -0,0 +1,476 @@
+//! Resumable JSON scanner shared by the slice and async parsers.
+//!
+//! The scanner validates grammar and limits and reports each token as a byte
+//! span of the caller's buffer. When a token runs past the end of the
+//! buffer it returns [`Scan::NeedMore`] without consuming it, remembering how
+//! far it already validated so the next call resumes rather than rescans.
+
+use super::{JsonError, JsonLimits};
+
+/// Kind of a scanned token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+    Key,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+}
+
+/// A token located in the scanned buffer.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Scanned {
+    pub(super) kind: Kind,
+    /// Start of the raw token, including the opening quote of a string.
+    pub(super) start: usize,
+    /// End of the raw token, including the closing quote of a string.
+    pub(super) end: usize,
+    /// Whether a string token contains escapes that must be decoded.
+    pub(super) escaped: bool,
+}
+
+impl Scanned {
+    /// Returns the bytes between the quotes of a string token.
+    pub(super) fn contents(self) -> std::ops::Range<usize> {
+        match self.kind {
+            Kind::Key | Kind::String => self.start + 1..self.end - 1,
+            _ => self.start..self.end,
+        }
+    }
+}
+
+/// Outcome of one [`Scanner::scan`] call.
+#[derive(Debug)]
+pub(super) enum Scan {
+    Token(Scanned),
+    NeedMore,
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Value,
+    ValueOrEnd,
+    KeyOrEnd,
+    Key,
+    Colon,
+    CommaOrEnd,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Debug)]
+pub(super) struct Scanner {
+    limits: JsonLimits,
+    stack: Vec<Container>,
+    expect: Expect,
+    containers: u64,
+    /// Absolute input offset of the caller's `buf[0]`.
+    base: u64,
+    /// Bytes of the current token already validated by an earlier call.
+    partial: usize,
+    partial_escaped: bool,
+}
+
+impl Scanner {
+    pub(super) const fn new(limits: JsonLimits) -> Self {
+        Self {
+            limits,
+            stack: Vec::new(),
+            expect: Expect::Value,
+            containers: 0,
+            base: 0,
+            partial: 0,
+            partial_escaped: false,
+        }
+    }
+
+    pub(super) const fn limits(&self) -> &JsonLimits {
+        &self.limits
+    }
+
+    /// Records that the caller dropped `n` bytes from the front of its
+    /// buffer, so error offsets stay absolute.
+    pub(super) const fn discard(&mut self, n: usize) {
+        self.base += n as u64;
+    }
+
+    /// Returns the absolute input offset of `buf[at]`.
+    pub(super) const fn offset(&self, at: usize) -> u64 {
+        self.base + at as u64
+    }
+
+    /// Scans the next token of `buf` starting at `*pos`.
+    ///
+    /// `eof` says whether `buf` holds the rest of the input; without it, a
+    /// token touching the end of `buf` is incomplete.
+    pub(super) fn scan(
+        &mut self,
+        buf: &[u8],
+        pos: &mut usize,
+        eof: bool,
+    ) -> Result<Scan, JsonError> {
+        loop {
+            while buf
+                .get(*pos)
+                .is_some_and(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+            {
+                *pos += 1;
+            }
+            let Some(&byte) = buf.get(*pos) else {
+                return if !eof {
+                    Ok(Scan::NeedMore)
+                } else if self.expect == Expect::Done {
+                    Ok(Scan::End)
+                } else {
+                    Err(self.eof_error(*pos))
+                };
+            };
+            match self.expect {
+                Expect::Done => {
+                    return Err(self.syntax(*pos, "trailing characters after document"));
+                }
+                Expect::Colon => {
+                    if byte != b':' {
+                        return Err(self.syntax(*pos, "expected `:` after object key"));
+                    }
+                    *pos += 1;
+                    self.expect = Expect::Value;
+                }
+                Expect::CommaOrEnd => match byte {
+                    b',' => {
+                        *pos += 1;
+                        self.expect = match self.stack.last() {
+                            Some(Container::Object) => Expect::Key,
+                            _ => Expect::Value,
+                        };
+                    }
+                    b'}' | b']' => return self.close(byte, pos),
+                    _ => return Err(self.syntax(*pos, "expected `,` or end of container")),
+                },
+                Expect::KeyOrEnd if byte == b'}' => return self.close(byte, pos),
+                Expect::ValueOrEnd if byte == b']' => return self.close(byte, pos),
+                Expect::KeyOrEnd | Expect::Key => {
+                    if byte != b'"' {
+                        return Err(self.syntax(*pos, "expected a string object key"));
+                    }
+                    return self.string(buf, pos, eof, Kind::Key);
+                }
+                Expect::Value | Expect::ValueOrEnd => return self.value(buf, pos, eof, byte),
+            }
+        }
+    }
+
+    fn value(
+        &mut self,
+        buf: &[u8],
+        pos: &mut usize,
+        eof: bool,
+        byte: u8,
+    ) -> Result<Scan, JsonError> {
+        match byte {
+            b'{' => self.open(Container::Object, pos),
+            b'[' => self.open(Container::Array, pos),
+            b'"' => self.string(buf, pos, eof, Kind::String),
+            b'-' | b'0'..=b'9' => self.number(buf, pos, eof),
+            b't' => self.literal(buf, pos, eof, b"true", Kind::True),
+            b'f' => self.literal(buf, pos, eof, b"false", Kind::False),
+            b'n' => self.literal(buf, pos, eof, b"null", Kind::Null),
+            _ => Err(self.syntax(*pos, "expected a value")),
+        }
+    }
+
+    fn open(&mut self, container: Container, pos: &mut usize) -> Result<Scan, JsonError> {
+        if self.stack.len() >= self.limits.max_depth {
+            return Err(JsonError::DepthLimitExceeded {
+                limit: self.limits.max_depth,
+            });
+        }
+        if self.containers >= self.limits.max_containers {
+            return Err(JsonError::ContainerLimitExceeded {
+                limit: self.limits.max_containers,
+            });
+        }
+        self.containers += 1;
+        self.stack.push(container);
+        let (kind, expect) = match container {
+            Container::Object => (Kind::BeginObject, Expect::KeyOrEnd),
+            Container::Array => (Kind::BeginArray, Expect::ValueOrEnd),
+        };
+        self.expect = expect;
+        Ok(self.emit(kind, pos, *pos + 1, false))
+    }
+
+    fn close(&mut self, byte: u8, pos: &mut usize) -> Result<Scan, JsonError> {
+        let kind = match (self.stack.last(), byte) {
+            (Some(Container::Object), b'}') => Kind::EndObject,
+            (Some(Container::Array), b']') => Kind::EndArray,
+            _ => return Err(self.syntax(*pos, "mismatched closing bracket")),
+        };
+        self.stack.pop();
+        self.expect = self.after_value();
+        Ok(self.emit(kind, pos, *pos + 1, false))
+    }
+
+    fn string(
+        &mut self,
+        buf: &[u8],
+        pos: &mut usize,
+        eof: bool,
+        kind: Kind,
+    ) -> Result<Scan, JsonError> {
+        let start = *pos;
+        let limit = self.limits.max_string_len;
+        let mut i = start + 1 + self.partial;
+        let mut escaped = self.partial_escaped;
+        loop {
+            if i - start - 1 > limit {
+                return Err(self.fail(JsonError::StringTooLong { limit }));
+            }
+            let Some(&b) = buf.get(i) else { break };
+            match b {
+                b'"' => {
+                    let contents = &buf[start + 1..i];
+                    if std::str::from_utf8(contents).is_err() {
+                        return Err(self.syntax(start, "string is not valid UTF-8"));
+                    }
+                    self.expect = match kind {
+                        Kind::Key => Expect::Colon,
+                        _ => self.after_value(),
+                    };
+                    return Ok(self.emit(kind, pos, i + 1, escaped));
+                }
+                b'\\' => {
+                    escaped = true;
+                    match escape_len(&buf[i..]) {
+                        Ok(Some(len)) => i += len,
+                        Ok(None) => break,
+                        Err(reason) => return Err(self.syntax(i, reason)),
+                    }
+                }
+                0x00..=0x1f => return Err(self.syntax(i, "control character in string")),
+                _ => i += 1,
+            }
+        }
+        if eof {
+            return Err(self.eof_error(buf.len()));
+        }
+        self.partial = i - start - 1;
+        self.partial_escaped = escaped;
+        Ok(Scan::NeedMore)
+    }
+
+    fn number(&mut self, buf: &[u8], pos: &mut usize, eof: bool) -> Result<Scan, JsonError> {
+        let start = *pos;
+        let limit = self.limits.max_number_len;
+        let mut i = start + self.partial;
+        while buf
+            .get(i)
+            .is_some_and(|b| matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+        {
+            i += 1;
+            if i - start > limit {
+                return Err(self.fail(JsonError::NumberTooLong { limit }));
+            }
+        }
+        if i == buf.len() && !eof {
+            self.partial = i - start;
+            return Ok(Scan::NeedMore);
+        }
+        if !is_valid_number(&buf[start..i]) {
+            return Err(self.syntax(start, "invalid number"));
+        }
+        self.expect = self.after_value();
+        Ok(self.emit(Kind::Number, pos, i, false))
+    }
+
+    fn literal(
+        &mut self,
+        buf: &[u8],
+        pos: &mut usize,
+        eof: bool,
+        literal: &[u8],
+        kind: Kind,
+    ) -> Result<Scan, JsonError> {
+        let start = *pos;
+        let available = &buf[start..buf.len().min(start + literal.len())];
+        if !literal.starts_with(available) {
+            return Err(self.syntax(start, "invalid literal"));
+        }
+        if available.len() < literal.len() {
+            if eof {
+                return Err(self.eof_error(buf.len()));
+            }
+            return Ok(Scan::NeedMore);
+        }
+        self.expect = self.after_value();
+        Ok(self.emit(kind, pos, start + literal.len(), false))
+    }
+
+    fn emit(&mut self, kind: Kind, pos: &mut usize, end: usize, escaped: bool) -> Scan {
+        let start = *pos;
+        *pos = end;
+        self.partial = 0;
+        self.partial_escaped = false;
+        Scan::Token(Scanned {
+            kind,
+            start,
+            end,
+            escaped,
+        })
+    }
+
+    fn after_value(&self) -> Expect {
+        if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::CommaOrEnd
+        }
+    }
+
+    fn fail(&mut self, err: JsonError) -> JsonError {
+        self.partial = 0;
+        self.partial_escaped = false;
+        err
+    }
+
+    fn syntax(&mut self, at: usize, reason: &'static str) -> JsonError {
+        let offset = self.offset(at);
+        self.fail(JsonError::Syntax { offset, reason })
+    }
+
+    fn eof_error(&mut self, at: usize) -> JsonError {
+        let offset = self.offset(at);
+        self.fail(JsonError::UnexpectedEof { offset })
+    }
+}
+
+/// Validates the escape sequence at the start of `s` (which begins with a
+/// backslash) and returns its length, or `None` if `s` ends inside it.
+///
+/// A high surrogate must be followed by an escaped low surrogate; the pair
+/// is validated as one escape.
+fn escape_len(s: &[u8]) -> Result<Option<usize>, &'static str> {
+    let Some(&kind) = s.get(1) else {
+        return Ok(None);
+    };
+    match kind {
+        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => Ok(Some(2)),
+        b'u' => match hex_escape(&s[2..])? {
+            None => Ok(None),
+            Some(0xD800..=0xDBFF) => {
+                let rest = &s[6..];
+                match rest {
+                    [] | [b'\\'] => return Ok(None),
+                    [b'\\', b'u', ..] => {}
+                    _ => return Err("unpaired surrogate in unicode escape"),
+                }
+                match hex_escape(&rest[2..])? {
+                    None => Ok(None),
+                    Some(0xDC00..=0xDFFF) => Ok(Some(12)),
+                    Some(_) => Err("unpaired surrogate in unicode escape"),
+                }
+            }
+            Some(0xDC00..=0xDFFF) => Err("unpaired surrogate in unicode escape"),
+            Some(_) => Ok(Some(6)),
+        },
+        _ => Err("invalid escape"),
+    }
+}
+
+/// Parses the four hex digits of a `\u` escape, or `None` if `s` is short.
+fn hex_escape(s: &[u8]) -> Result<Option<u32>, &'static str> {
+    let mut value = 0;
+    for index in 0..4 {
+        let Some(&digit) = s.get(index) else {
+            return Ok(None);
+        };
+        let digit = char::from(digit)
+            .to_digit(16)
+            .ok_or("invalid unicode escape")?;
+        value = value * 16 + digit;
+    }
+    Ok(Some(value))
+}
+
+/// Decodes the validated contents of an escaped string into `out`.
+pub(super) fn unescape(contents: &str, out: &mut String) {
+    out.clear();
+    let mut rest = contents;
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+        let bytes = &rest.as_bytes()[index..];
+        let (c, len) = match bytes[1] {
+            b'b' => ('\u{8}', 2),
+            b'f' => ('\u{c}', 2),
+            b'n' => ('\n', 2),
+            b'r' => ('\r', 2),
+            b't' => ('\t', 2),
+            b'u' => {
+                let high = hex_value(&bytes[2..6]);
+                if (0xD800..=0xDBFF).contains(&high) {
+                    let low = hex_value(&bytes[8..12]);
+                    let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                    (char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER), 12)
+                } else {
+                    (char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER), 6)
+                }
+            }
+            other => (char::from(other), 2),
+        };
+        out.push(c);
+        rest = &rest[index + len..];
+    }
+    out.push_str(rest);
+}
+
+fn hex_value(digits: &[u8]) -> u32 {
+    let mut value = 0;
+    for &digit in digits {
+        value = value * 16 + char::from(digit).to_digit(16).unwrap_or(0);
+    }
+    value
+}
+
+/// Checks `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`.
+fn is_valid_number(s: &[u8]) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    let mut i = usize::from(s.first() == Some(&b'-'));
+    match s.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => i += digits(&s[i..]),
+        _ => return false,
+    }
+    if s.get(i) == Some(&b'.') {
+        let fraction = digits(&s[i + 1..]);
+        if fraction == 0 {
+            return false;
+        }
+        i += 1 + fraction;
+    }
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(s.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        let exponent = digits(&s[i..]);
+        if exponent == 0 {
+            return false;
+        }
+        i += exponent;
+    }
+    i == s.len()
+}
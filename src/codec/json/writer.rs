@@ -0,0 +1,320 @@
+//! Incremental JSON serialization to an async writer.
+
+use serde::Serialize;
+
+use super::JsonError;
+use crate::io::{AsyncWrite, AsyncWriteExt};
+
+/// Pending output, in bytes, that triggers a write by default.
+const DEFAULT_FLUSH_THRESHOLD: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Debug)]
+struct Frame {
+    container: Container,
+    empty: bool,
+    has_key: bool,
+}
+
+/// Streaming JSON serializer over an [`AsyncWrite`].
+///
+/// Objects and arrays are opened and closed explicitly, and each element is
+/// serialized on its own with serde. Only the element being written and up
+/// to the flush threshold of pending output are held in memory, so an array
+/// fed from an iterator can be arbitrarily long. A single element is still
+/// materialized whole; split large elements into nested calls.
+///
+/// After an error the output is incomplete and the writer should be
+/// dropped.
+#[derive(Debug)]
+pub struct JsonWriter<W> {
+    writer: W,
+    buf: Vec<u8>,
+    flush_threshold: usize,
+    stack: Vec<Frame>,
+    root_written: bool,
+}
+
+impl<W> JsonWriter<W> {
+    /// Creates a writer.
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: Vec::new(),
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+            stack: Vec::new(),
+            root_written: false,
+        }
+    }
+
+    /// Sets how much output is buffered before it is written (default
+    /// 8 KiB).
+    #[must_use]
+    pub const fn with_flush_threshold(mut self, flush_threshold: usize) -> Self {
+        self.flush_threshold = flush_threshold;
+        self
+    }
+
+    /// Returns the capacity of the pending-output buffer, for monitoring
+    /// memory use.
+    #[must_use]
+    pub fn buffer_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Opens an object where a value is expected.
+    pub fn begin_object(&mut self) -> Result<(), JsonError> {
+        self.open(Container::Object, b'{')
+    }
+
+    /// Opens an array where a value is expected.
+    pub fn begin_array(&mut self) -> Result<(), JsonError> {
+        self.open(Container::Array, b'[')
+    }
+
+    /// Writes the key of the next field in the innermost object.
+    pub fn key(&mut self, key: &str) -> Result<(), JsonError> {
+        let Some(frame) = self.stack.last_mut() else {
+            return Err(JsonError::WriterState("key outside an object"));
+        };
+        if frame.container != Container::Object {
+            return Err(JsonError::WriterState("key outside an object"));
+        }
+        if frame.has_key {
+            return Err(JsonError::WriterState("key written twice"));
+        }
+        if !frame.empty {
+            self.buf.push(b',');
+        }
+        frame.empty = false;
+        frame.has_key = true;
+        serde_json::to_writer(&mut self.buf, key)?;
+        self.buf.push(b':');
+        Ok(())
+    }
+
+    fn open(&mut self, container: Container, byte: u8) -> Result<(), JsonError> {
+        self.before_value()?;
+        self.buf.push(byte);
+        self.stack.push(Frame {
+            container,
+            empty: true,
+            has_key: false,
+        });
+        Ok(())
+    }
+
+    fn before_value(&mut self) -> Result<(), JsonError> {
+        match self.stack.last_mut() {
+            None if self.root_written => Err(JsonError::WriterState("document already complete")),
+            None => {
+                self.root_written = true;
+                Ok(())
+            }
+            Some(frame) if frame.container == Container::Array => {
+                if !frame.empty {
+                    self.buf.push(b',');
+                }
+                frame.empty = false;
+                Ok(())
+            }
+            Some(frame) if frame.has_key => {
+                frame.has_key = false;
+                Ok(())
+            }
+            Some(_) => Err(JsonError::WriterState("object value without a key")),
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> JsonWriter<W> {
+    /// Serializes `value` where a value is expected.
+    pub async fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.before_value()?;
+        serde_json::to_writer(&mut self.buf, value)?;
+        self.maybe_flush().await
+    }
+
+    /// Writes one object field.
+    pub async fn field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), JsonError> {
+        self.key(key)?;
+        self.value(value).await
+    }
+
+    /// Writes an array whose elements are pulled from `items` one at a time.
+    pub async fn array<I>(&mut self, items: I) -> Result<(), JsonError>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        self.begin_array()?;
+        for item in items {
+            self.value(&item).await?;
+        }
+        self.end_array().await
+    }
+
+    /// Closes the innermost object.
+    pub async fn end_object(&mut self) -> Result<(), JsonError> {
+        self.close(Container::Object, b'}').await
+    }
+
+    /// Closes the innermost array.
+    pub async fn end_array(&mut self) -> Result<(), JsonError> {
+        self.close(Container::Array, b']').await
+    }
+
+    /// Writes any pending output and flushes the underlying writer.
+    pub async fn flush(&mut self) -> Result<(), JsonError> {
+        self.write_pending().await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Flushes the completed document and returns the underlying writer.
+    pub async fn finish(mut self) -> Result<W, JsonError> {
+        if !self.root_written || !self.stack.is_empty() {
+            return Err(JsonError::WriterState("document is incomplete"));
+        }
+        self.flush().await?;
+        Ok(self.writer)
+    }
+
+    async fn close(&mut self, container: Container, byte: u8) -> Result<(), JsonError> {
+        match self.stack.last() {
+            Some(frame) if frame.container == container && !frame.has_key => {
+                self.stack.pop();
+                self.buf.push(byte);
+                self.maybe_flush().await
+            }
+            Some(frame) if frame.container == container => {
+                Err(JsonError::WriterState("object closed after a key"))
+            }
+            _ => Err(JsonError::WriterState("mismatched end of container")),
+        }
+    }
+
+    async fn maybe_flush(&mut self) -> Result<(), JsonError> {
+        if self.buf.len() >= self.flush_threshold {
+            self.write_pending().await?;
+        }
+        Ok(())
+    }
+
+    async fn write_pending(&mut self) -> Result<(), JsonError> {
+        self.writer.write_all(&self.buf).await?;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::codec::json::{JsonPath, JsonReader};
+    use serde::Deserialize;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        id: u32,
+        label: String,
+    }
+
+    #[test]
+    fn streamed_document_round_trips_through_reader() {
+        init_test("streamed_document_round_trips_through_reader");
+        let rows = (0..20_000).map(|id| Row {
+            id,
+            label: format!("row-{id}"),
+        });
+        let (output, max_capacity) = futures_lite::future::block_on(async {
+            let mut writer = JsonWriter::new(Vec::new()).with_flush_threshold(1024);
+            writer.begin_object().expect("open root");
+            writer.field("count", &20_000).await.expect("count");
+            writer.key("rows").expect("rows key");
+            writer.array(rows).await.expect("rows");
+            writer.field("done", &true).await.expect("done");
+            writer.end_object().await.expect("close root");
+            let max_capacity = writer.buffer_capacity();
+            (writer.finish().await.expect("complete"), max_capacity)
+        });
+        crate::assert_with_log!(max_capacity < 4096, "buffer bounded", "< 4 KiB", max_capacity);
+
+        let value: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+        crate::assert_with_log!(value["count"] == 20_000, "count", 20_000, value["count"]);
+        crate::assert_with_log!(value["done"] == true, "done", true, value["done"]);
+
+        let ids = futures_lite::future::block_on(async {
+            let path = JsonPath::parse("$.rows[*]").expect("valid path");
+            let mut reader = JsonReader::new(output.as_slice());
+            let mut selection = reader.select(&path);
+            let mut ids = Vec::new();
+            while let Some(row) = selection.next::<Row>().await.expect("valid row") {
+                assert_eq!(row.label, format!("row-{}", row.id));
+                ids.push(row.id);
+            }
+            ids
+        });
+        let expected: Vec<u32> = (0..20_000).collect();
+        crate::assert_with_log!(ids == expected, "row ids", expected.len(), ids.len());
+        crate::test_complete!("streamed_document_round_trips_through_reader");
+    }
+
+    #[test]
+    fn out_of_order_calls_are_rejected() {
+        init_test("out_of_order_calls_are_rejected");
+        futures_lite::future::block_on(async {
+            let mut writer = JsonWriter::new(Vec::new());
+            writer.begin_object().expect("open root");
+            let result = writer.value(&1).await;
+            let rejected = matches!(result, Err(JsonError::WriterState(_)));
+            crate::assert_with_log!(rejected, "value without key", "WriterState", result);
+
+            let result = writer.end_array().await;
+            let rejected = matches!(result, Err(JsonError::WriterState(_)));
+            crate::assert_with_log!(rejected, "mismatched end", "WriterState", result);
+
+            let mut writer = JsonWriter::new(Vec::new());
+            writer.begin_array().expect("open root");
+            let result = writer.key("k");
+            let rejected = matches!(result, Err(JsonError::WriterState(_)));
+            crate::assert_with_log!(rejected, "key in array", "WriterState", result);
+            let result = writer.finish().await;
+            let rejected = matches!(result, Err(JsonError::WriterState(_)));
+            crate::assert_with_log!(rejected, "incomplete document", "WriterState", result);
+
+            let mut writer = JsonWriter::new(Vec::new());
+            writer.value("root").await.expect("root value");
+            let result = writer.value("second").await;
+            let rejected = matches!(result, Err(JsonError::WriterState(_)));
+            crate::assert_with_log!(rejected, "second root", "WriterState", result);
+            let output = writer.finish().await.expect("complete");
+            crate::assert_with_log!(output == b"\"root\"", "output", "\"root\"", output);
+        });
+        crate::test_complete!("out_of_order_calls_are_rejected");
+    }
+}
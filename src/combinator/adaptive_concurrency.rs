@@ -0,0 +1,911 @@
+//! Adaptive concurrency limiting for outbound calls.
+//!
+//! A static concurrency cap is either too low (wasted capacity) or too high
+//! (a browned-out downstream gets buried in retries). [`AdaptiveLimiter`]
+//! instead maintains a dynamic limit that a pluggable [`LimitAlgorithm`]
+//! adjusts from observed latency and failures, clamped to the configured
+//! `[min_limit, max_limit]` range.
+//!
+//! Two algorithms are built in:
+//!
+//! - [`Aimd`]: additive increase while calls succeed under a latency target,
+//!   multiplicative decrease on failures, timeouts or slow responses.
+//! - [`Gradient`]: a Vegas-style estimator that compares a short-term latency
+//!   average to a long-term baseline and shrinks the limit as queueing delay
+//!   builds up.
+//!
+//! Every admitted call holds an [`AdaptivePermit`]. Permits are linear
+//! obligations: each one is resolved exactly once, either by recording an
+//! outcome or by being dropped (e.g. on cancellation), which releases the slot
+//! without feeding a sample to the algorithm. Calls beyond the current limit
+//! are rejected immediately with [`LimitRejected`] rather than queued.
+//!
+//! All timestamps are supplied by the caller, so lab runs driven by the
+//! virtual clock produce the same limit trajectory on every replay.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::combinator::adaptive_concurrency::*;
+//! use std::sync::Arc;
+//!
+//! let limiter = Arc::new(AdaptiveLimiter::new(
+//!     AdaptiveLimitConfig::default(),
+//!     Aimd::default(),
+//! ));
+//!
+//! match limiter.try_acquire(now) {
+//!     Ok(permit) => match call_downstream().await {
+//!         Ok(response) => {
+//!             permit.success(cx.now());
+//!             Ok(response)
+//!         }
+//!         Err(error) => {
+//!             permit.failure(cx.now());
+//!             Err(error)
+//!         }
+//!     },
+//!     Err(rejected) => Err(rejected.into()), // fail fast; let the retry layer back off
+//! }
+//! ```
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::types::Time;
+
+// =========================================================================
+// Samples & Algorithms
+// =========================================================================
+
+/// How a completed call should feed the limit algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitOutcome {
+    /// The downstream answered normally.
+    Success,
+    /// The downstream failed or timed out; a sign of overload.
+    Failure,
+    /// Release the slot without adjusting the limit (cancellation, client
+    /// errors, anything that says nothing about downstream health).
+    Ignore,
+}
+
+/// A completed call, as seen by a [`LimitAlgorithm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitSample {
+    /// Time from admission to completion.
+    pub latency: Duration,
+    /// Calls in flight when this one was admitted, including itself.
+    pub in_flight: u32,
+    /// Whether the call succeeded or failed. Never [`LimitOutcome::Ignore`].
+    pub outcome: LimitOutcome,
+}
+
+/// Strategy that derives the next concurrency limit from completed calls.
+///
+/// Implementations must be deterministic functions of their own state and
+/// the samples they are fed; the limiter clamps the returned value to its
+/// configured bounds.
+pub trait LimitAlgorithm: fmt::Debug + Send {
+    /// Returns the new limit after observing `sample` at the current `limit`.
+    fn update(&mut self, limit: u32, sample: &LimitSample) -> u32;
+}
+
+/// Additive-increase / multiplicative-decrease limit algorithm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aimd {
+    /// Amount the limit grows per healthy sample.
+    pub increase_by: u32,
+    /// Factor applied to the limit on failure or slow response (0, 1).
+    pub backoff_ratio: f64,
+    /// Successful calls slower than this count as overload.
+    pub latency_target: Duration,
+}
+
+impl Default for Aimd {
+    fn default() -> Self {
+        Self {
+            increase_by: 1,
+            backoff_ratio: 0.9,
+            latency_target: Duration::from_millis(100),
+        }
+    }
+}
+
+impl LimitAlgorithm for Aimd {
+    fn update(&mut self, limit: u32, sample: &LimitSample) -> u32 {
+        let overloaded = match sample.outcome {
+            LimitOutcome::Failure => true,
+            LimitOutcome::Success => sample.latency > self.latency_target,
+            LimitOutcome::Ignore => return limit,
+        };
+        if overloaded {
+            let reduced = (f64::from(limit) * self.backoff_ratio) as u32;
+            reduced.min(limit.saturating_sub(1))
+        } else if sample.in_flight.saturating_mul(2) >= limit {
+            // Only grow while the limit is actually being used; an idle
+            // limiter has no evidence the downstream can take more.
+            limit.saturating_add(self.increase_by)
+        } else {
+            limit
+        }
+    }
+}
+
+/// Vegas-style gradient limit algorithm.
+///
+/// Tracks a short-term and a long-term exponentially weighted latency
+/// average. When short-term latency rises above the long-term baseline,
+/// requests are queueing downstream and the gradient `long / short` pulls
+/// the limit down; while they agree, the limit grows by a `sqrt(limit)`
+/// queue allowance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// Samples in the short-term latency window.
+    pub short_window: u32,
+    /// Samples in the long-term latency window.
+    pub long_window: u32,
+    /// How much short-term latency may exceed the baseline before the
+    /// limit shrinks (e.g. `1.5` tolerates a 50% rise).
+    pub tolerance: f64,
+    /// Weight of each new estimate in the smoothed limit, in (0, 1].
+    pub smoothing: f64,
+    short_nanos: f64,
+    long_nanos: f64,
+    estimate: Option<f64>,
+}
+
+impl Gradient {
+    /// Creates a gradient algorithm with explicit windows.
+    #[must_use]
+    pub fn new(short_window: u32, long_window: u32) -> Self {
+        Self {
+            short_window: short_window.max(1),
+            long_window: long_window.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the latency tolerance.
+    #[must_use]
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the smoothing factor.
+    #[must_use]
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    fn ewma(previous: f64, sample: f64, window: u32) -> f64 {
+        if previous == 0.0 {
+            return sample;
+        }
+        let alpha = 2.0 / (f64::from(window) + 1.0);
+        previous + alpha * (sample - previous)
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            short_window: 10,
+            long_window: 600,
+            tolerance: 1.5,
+            smoothing: 0.2,
+            short_nanos: 0.0,
+            long_nanos: 0.0,
+            estimate: None,
+        }
+    }
+}
+
+impl LimitAlgorithm for Gradient {
+    fn update(&mut self, limit: u32, sample: &LimitSample) -> u32 {
+        // Resync when the limiter clamped the previous estimate to its bounds.
+        let current = match self.estimate {
+            Some(estimate) if estimate as u32 == limit => estimate,
+            _ => f64::from(limit),
+        };
+        let gradient = match sample.outcome {
+            LimitOutcome::Ignore => return limit,
+            // A failure says nothing reliable about latency; back off hard.
+            LimitOutcome::Failure => 0.5,
+            LimitOutcome::Success => {
+                let rtt = sample.latency.as_nanos() as f64;
+                self.short_nanos = Self::ewma(self.short_nanos, rtt, self.short_window);
+                self.long_nanos = Self::ewma(self.long_nanos, rtt, self.long_window);
+                // Let the baseline drift down after a sustained shift so a
+                // permanently slower downstream is not punished forever.
+                if self.long_nanos > 2.0 * self.short_nanos {
+                    self.long_nanos *= 0.95;
+                }
+                if self.short_nanos <= 0.0 {
+                    1.0
+                } else {
+                    (self.tolerance * self.long_nanos / self.short_nanos).clamp(0.5, 1.0)
+                }
+            }
+        };
+        // Only grant queue headroom while the limit is in use.
+        let queue = if sample.in_flight.saturating_mul(2) >= limit {
+            current.sqrt()
+        } else {
+            0.0
+        };
+        let target = current.mul_add(gradient, queue);
+        let next = current.mul_add(1.0 - self.smoothing, target * self.smoothing);
+        self.estimate = Some(next.max(1.0));
+        next as u32
+    }
+}
+
+// =========================================================================
+// Configuration, Metrics & Errors
+// =========================================================================
+
+/// Callback invoked on every limit change.
+pub type AdjustmentCallback = Arc<dyn Fn(&LimitAdjustment) + Send + Sync>;
+
+/// Adaptive limiter configuration.
+#[derive(Clone)]
+pub struct AdaptiveLimitConfig {
+    /// Limit before any samples are observed.
+    pub initial_limit: u32,
+    /// Lower bound for the limit (at least 1).
+    pub min_limit: u32,
+    /// Upper bound for the limit.
+    pub max_limit: u32,
+    /// Number of recent [`LimitAdjustment`]s retained for inspection.
+    pub adjustment_history: usize,
+    /// Callback invoked on every limit change.
+    pub on_adjust: Option<AdjustmentCallback>,
+}
+
+impl AdaptiveLimitConfig {
+    /// Sets the initial limit.
+    #[must_use]
+    pub fn initial_limit(mut self, limit: u32) -> Self {
+        self.initial_limit = limit;
+        self
+    }
+
+    /// Sets the limit bounds.
+    #[must_use]
+    pub fn bounds(mut self, min_limit: u32, max_limit: u32) -> Self {
+        self.min_limit = min_limit;
+        self.max_limit = max_limit;
+        self
+    }
+
+    /// Sets the limit-change callback.
+    #[must_use]
+    pub fn on_adjust(mut self, callback: AdjustmentCallback) -> Self {
+        self.on_adjust = Some(callback);
+        self
+    }
+
+    fn clamp(&self, limit: u32) -> u32 {
+        let min = self.min_limit.max(1);
+        limit.clamp(min, self.max_limit.max(min))
+    }
+}
+
+impl fmt::Debug for AdaptiveLimitConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdaptiveLimitConfig")
+            .field("initial_limit", &self.initial_limit)
+            .field("min_limit", &self.min_limit)
+            .field("max_limit", &self.max_limit)
+            .field("adjustment_history", &self.adjustment_history)
+            .field("on_adjust", &self.on_adjust.is_some())
+            .finish()
+    }
+}
+
+impl Default for AdaptiveLimitConfig {
+    fn default() -> Self {
+        Self {
+            initial_limit: 20,
+            min_limit: 1,
+            max_limit: 1_000,
+            adjustment_history: 256,
+            on_adjust: None,
+        }
+    }
+}
+
+/// A recorded change of the concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitAdjustment {
+    /// When the sample that triggered the change completed.
+    pub at: Time,
+    /// Limit before the change.
+    pub previous: u32,
+    /// Limit after the change.
+    pub limit: u32,
+    /// The sample that triggered the change.
+    pub sample: LimitSample,
+}
+
+/// Metrics exposed by an adaptive limiter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveLimitMetrics {
+    /// Current concurrency limit.
+    pub limit: u32,
+    /// Calls currently holding a permit.
+    pub in_flight: u32,
+    /// Highest in-flight count observed.
+    pub peak_in_flight: u32,
+    /// Total calls admitted.
+    pub total_admitted: u64,
+    /// Total calls rejected at the limit.
+    pub total_rejected: u64,
+    /// Total permits resolved as success.
+    pub total_success: u64,
+    /// Total permits resolved as failure.
+    pub total_failure: u64,
+    /// Total permits released without a sample (ignored or dropped).
+    pub total_ignored: u64,
+    /// Number of limit increases.
+    pub increases: u64,
+    /// Number of limit decreases.
+    pub decreases: u64,
+}
+
+/// Error returned when a call is rejected because the limit is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitRejected {
+    /// The limit at rejection time.
+    pub limit: u32,
+    /// Calls in flight at rejection time.
+    pub in_flight: u32,
+}
+
+impl fmt::Display for LimitRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "adaptive concurrency limit reached ({}/{} in flight)",
+            self.in_flight, self.limit
+        )
+    }
+}
+
+impl std::error::Error for LimitRejected {}
+
+// =========================================================================
+// Core Implementation
+// =========================================================================
+
+#[derive(Debug)]
+struct LimiterState {
+    limit: u32,
+    algorithm: Box<dyn LimitAlgorithm>,
+    metrics: AdaptiveLimitMetrics,
+    adjustments: VecDeque<LimitAdjustment>,
+}
+
+/// Thread-safe adaptive concurrency limiter.
+///
+/// Share it behind an [`Arc`]; permits keep the limiter alive until they
+/// are resolved.
+pub struct AdaptiveLimiter {
+    config: AdaptiveLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+impl AdaptiveLimiter {
+    /// Creates a limiter driven by `algorithm`.
+    #[must_use]
+    pub fn new(config: AdaptiveLimitConfig, algorithm: impl LimitAlgorithm + 'static) -> Self {
+        let limit = config.clamp(config.initial_limit);
+        Self {
+            state: Mutex::new(LimiterState {
+                limit,
+                algorithm: Box::new(algorithm),
+                metrics: AdaptiveLimitMetrics {
+                    limit,
+                    ..AdaptiveLimitMetrics::default()
+                },
+                adjustments: VecDeque::new(),
+            }),
+            config,
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &AdaptiveLimitConfig {
+        &self.config
+    }
+
+    /// Returns the current concurrency limit.
+    #[must_use]
+    pub fn limit(&self) -> u32 {
+        self.state.lock().limit
+    }
+
+    /// Returns the number of calls currently holding a permit.
+    #[must_use]
+    pub fn in_flight(&self) -> u32 {
+        self.state.lock().metrics.in_flight
+    }
+
+    /// Returns a metrics snapshot.
+    #[must_use]
+    pub fn metrics(&self) -> AdaptiveLimitMetrics {
+        self.state.lock().metrics.clone()
+    }
+
+    /// Returns the retained limit changes, oldest first.
+    #[must_use]
+    pub fn adjustments(&self) -> Vec<LimitAdjustment> {
+        self.state.lock().adjustments.iter().copied().collect()
+    }
+
+    /// Admits a call if fewer than `limit` calls are in flight.
+    ///
+    /// `now` is the admission time used to measure the call's latency.
+    pub fn try_acquire(self: &Arc<Self>, now: Time) -> Result<AdaptivePermit, LimitRejected> {
+        let in_flight = {
+            let mut state = self.state.lock();
+            let in_flight = state.metrics.in_flight;
+            if in_flight >= state.limit {
+                state.metrics.total_rejected += 1;
+                return Err(LimitRejected {
+                    limit: state.limit,
+                    in_flight,
+                });
+            }
+            let in_flight = in_flight + 1;
+            state.metrics.in_flight = in_flight;
+            state.metrics.peak_in_flight = state.metrics.peak_in_flight.max(in_flight);
+            state.metrics.total_admitted += 1;
+            in_flight
+        };
+        Ok(AdaptivePermit {
+            limiter: Arc::clone(self),
+            admitted_at: now,
+            in_flight,
+            resolved: false,
+        })
+    }
+
+    fn release(&self, admitted_at: Time, in_flight: u32, outcome: LimitOutcome, now: Time) {
+        let adjustment = {
+            let mut state = self.state.lock();
+            state.metrics.in_flight = state.metrics.in_flight.saturating_sub(1);
+            match outcome {
+                LimitOutcome::Ignore => {
+                    state.metrics.total_ignored += 1;
+                    return;
+                }
+                LimitOutcome::Success => state.metrics.total_success += 1,
+                LimitOutcome::Failure => state.metrics.total_failure += 1,
+            }
+
+            let sample = LimitSample {
+                latency: Duration::from_nanos(now.duration_since(admitted_at)),
+                in_flight,
+                outcome,
+            };
+            let previous = state.limit;
+            let proposed = state.algorithm.update(previous, &sample);
+            let limit = self.config.clamp(proposed);
+            if limit == previous {
+                return;
+            }
+            state.limit = limit;
+            state.metrics.limit = limit;
+            if limit > previous {
+                state.metrics.increases += 1;
+            } else {
+                state.metrics.decreases += 1;
+            }
+            let adjustment = LimitAdjustment {
+                at: now,
+                previous,
+                limit,
+                sample,
+            };
+            if self.config.adjustment_history > 0 {
+                if state.adjustments.len() == self.config.adjustment_history {
+                    state.adjustments.pop_front();
+                }
+                state.adjustments.push_back(adjustment);
+            }
+            adjustment
+        };
+        // Run the callback outside the lock so it may inspect the limiter.
+        if let Some(callback) = &self.config.on_adjust {
+            callback(&adjustment);
+        }
+    }
+}
+
+impl fmt::Debug for AdaptiveLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("AdaptiveLimiter")
+            .field("limit", &state.limit)
+            .field("in_flight", &state.metrics.in_flight)
+            .field("algorithm", &state.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An admitted call's hold on an [`AdaptiveLimiter`] slot.
+///
+/// Resolve it with [`success`](Self::success), [`failure`](Self::failure) or
+/// [`record`](Self::record). Dropping an unresolved permit (for example when
+/// the call is cancelled) releases the slot as [`LimitOutcome::Ignore`].
+#[must_use = "dropping the permit releases the slot without a latency sample"]
+pub struct AdaptivePermit {
+    limiter: Arc<AdaptiveLimiter>,
+    admitted_at: Time,
+    in_flight: u32,
+    resolved: bool,
+}
+
+impl AdaptivePermit {
+    /// Returns when the call was admitted.
+    #[must_use]
+    pub fn admitted_at(&self) -> Time {
+        self.admitted_at
+    }
+
+    /// Resolves the permit with `outcome`, completing at `now`.
+    pub fn record(mut self, outcome: LimitOutcome, now: Time) {
+        self.resolved = true;
+        self.limiter
+            .release(self.admitted_at, self.in_flight, outcome, now);
+    }
+
+    /// Resolves the permit as a successful call completing at `now`.
+    pub fn success(self, now: Time) {
+        self.record(LimitOutcome::Success, now);
+    }
+
+    /// Resolves the permit as a failed or timed-out call at `now`.
+    pub fn failure(self, now: Time) {
+        self.record(LimitOutcome::Failure, now);
+    }
+}
+
+impl Drop for AdaptivePermit {
+    fn drop(&mut self) {
+        if !self.resolved {
+            self.limiter.release(
+                self.admitted_at,
+                self.in_flight,
+                LimitOutcome::Ignore,
+                self.admitted_at,
+            );
+        }
+    }
+}
+
+impl fmt::Debug for AdaptivePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdaptivePermit")
+            .field("admitted_at", &self.admitted_at)
+            .field("in_flight", &self.in_flight)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::DetRng;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn limiter(
+        config: AdaptiveLimitConfig,
+        algorithm: impl LimitAlgorithm + 'static,
+    ) -> Arc<AdaptiveLimiter> {
+        Arc::new(AdaptiveLimiter::new(config, algorithm))
+    }
+
+    /// Scripted downstream: healthy latency normally, slow and failing
+    /// during `[brownout_start, brownout_end)` ms of virtual time.
+    struct ScriptedDownstream {
+        rng: DetRng,
+        brownout_start: u64,
+        brownout_end: u64,
+    }
+
+    impl ScriptedDownstream {
+        fn new(seed: u64, brownout_start: u64, brownout_end: u64) -> Self {
+            Self {
+                rng: DetRng::new(seed),
+                brownout_start,
+                brownout_end,
+            }
+        }
+
+        /// Returns (latency_ms, outcome) for a call issued at `now_ms`.
+        fn respond(&mut self, now_ms: u64) -> (u64, LimitOutcome) {
+            let jitter = self.rng.next_u64() % 5;
+            if (self.brownout_start..self.brownout_end).contains(&now_ms) {
+                let outcome = if self.rng.next_u64() % 2 == 0 {
+                    LimitOutcome::Failure
+                } else {
+                    LimitOutcome::Success
+                };
+                (400 + jitter, outcome)
+            } else {
+                (20 + jitter, LimitOutcome::Success)
+            }
+        }
+    }
+
+    /// Drives a saturating client against the downstream in 1 ms virtual
+    /// steps and returns the limit sampled every 100 ms.
+    fn drive(
+        limiter: &Arc<AdaptiveLimiter>,
+        downstream: &mut ScriptedDownstream,
+        until_ms: u64,
+    ) -> Vec<u32> {
+        let mut pending: Vec<(u64, LimitOutcome, AdaptivePermit)> = Vec::new();
+        let mut trace = Vec::new();
+        for now_ms in 0..until_ms {
+            let now = Time::from_millis(now_ms);
+            let (done, still): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|(finish, _, _)| *finish <= now_ms);
+            pending = still;
+            for (_, outcome, permit) in done {
+                permit.record(outcome, now);
+            }
+            while let Ok(permit) = limiter.try_acquire(now) {
+                let (latency, outcome) = downstream.respond(now_ms);
+                pending.push((now_ms + latency, outcome, permit));
+            }
+            if now_ms % 100 == 0 {
+                trace.push(limiter.limit());
+            }
+        }
+        trace
+    }
+
+    #[test]
+    fn aimd_converges_upward_under_healthy_downstream() {
+        init_test("aimd_converges_upward_under_healthy_downstream");
+        let limiter = limiter(
+            AdaptiveLimitConfig::default()
+                .initial_limit(4)
+                .bounds(1, 64),
+            Aimd::default(),
+        );
+        let mut downstream = ScriptedDownstream::new(7, u64::MAX, u64::MAX);
+        let trace = drive(&limiter, &mut downstream, 2_000);
+
+        let last = *trace.last().expect("trace");
+        crate::assert_with_log!(last == 64, "limit reaches max", 64, last);
+        let monotone = trace.windows(2).all(|pair| pair[0] <= pair[1]);
+        crate::assert_with_log!(monotone, "healthy limit never shrinks", true, monotone);
+        crate::test_complete!("aimd_converges_upward_under_healthy_downstream");
+    }
+
+    #[test]
+    fn limit_collapses_and_recovers_around_brownout() {
+        init_test("limit_collapses_and_recovers_around_brownout");
+        let algorithms: Vec<(&str, Box<dyn Fn() -> Arc<AdaptiveLimiter>>)> = vec![
+            (
+                "aimd",
+                Box::new(|| {
+                    limiter(
+                        AdaptiveLimitConfig::default()
+                            .initial_limit(8)
+                            .bounds(2, 48),
+                        Aimd::default(),
+                    )
+                }),
+            ),
+            (
+                "gradient",
+                Box::new(|| {
+                    limiter(
+                        AdaptiveLimitConfig::default()
+                            .initial_limit(8)
+                            .bounds(2, 48),
+                        Gradient::new(5, 200),
+                    )
+                }),
+            ),
+        ];
+        for (name, make) in algorithms {
+            let limiter = make();
+            let mut downstream = ScriptedDownstream::new(42, 3_000, 4_000);
+            let trace = drive(&limiter, &mut downstream, 8_000);
+
+            let before = trace[29];
+            let during = trace[39];
+            let after = *trace.last().expect("trace");
+            crate::assert_with_log!(
+                during < before,
+                &format!("{name}: limit collapses during brownout"),
+                before,
+                during
+            );
+            crate::assert_with_log!(
+                after > during,
+                &format!("{name}: limit recovers after brownout"),
+                during,
+                after
+            );
+
+            // Same seed, same virtual times: identical trajectory.
+            let replay = make();
+            let mut downstream = ScriptedDownstream::new(42, 3_000, 4_000);
+            let replayed = drive(&replay, &mut downstream, 8_000);
+            crate::assert_with_log!(
+                replayed == trace,
+                &format!("{name}: deterministic"),
+                &trace,
+                &replayed
+            );
+            let (original, replayed) = (limiter.adjustments(), replay.adjustments());
+            crate::assert_with_log!(
+                replayed == original,
+                &format!("{name}: adjustment log replays"),
+                original.len(),
+                replayed.len()
+            );
+        }
+        crate::test_complete!("limit_collapses_and_recovers_around_brownout");
+    }
+
+    #[test]
+    fn limit_respects_bounds() {
+        init_test("limit_respects_bounds");
+        let limiter = limiter(
+            AdaptiveLimitConfig::default()
+                .initial_limit(100)
+                .bounds(3, 10),
+            Aimd::default(),
+        );
+        crate::assert_with_log!(
+            limiter.limit() == 10,
+            "initial clamped",
+            10,
+            limiter.limit()
+        );
+
+        for step in 0..50_u64 {
+            let permit = limiter.try_acquire(Time::from_millis(step)).expect("admit");
+            permit.failure(Time::from_millis(step + 1));
+        }
+        crate::assert_with_log!(limiter.limit() == 3, "floor holds", 3, limiter.limit());
+
+        let permits: Vec<_> = (0..3)
+            .map(|_| limiter.try_acquire(Time::ZERO).expect("admit"))
+            .collect();
+        let rejected = limiter.try_acquire(Time::ZERO).err();
+        let expected = Some(LimitRejected {
+            limit: 3,
+            in_flight: 3,
+        });
+        crate::assert_with_log!(rejected == expected, "excess rejected", expected, rejected);
+        for permit in permits {
+            permit.success(Time::from_millis(1));
+        }
+        for _ in 0..100 {
+            let held: Vec<_> = (0..limiter.limit())
+                .map(|_| limiter.try_acquire(Time::ZERO).expect("admit"))
+                .collect();
+            for permit in held {
+                permit.success(Time::from_millis(1));
+            }
+        }
+        crate::assert_with_log!(limiter.limit() == 10, "ceiling holds", 10, limiter.limit());
+        let metrics = limiter.metrics();
+        crate::assert_with_log!(
+            metrics.peak_in_flight <= 10,
+            "peak within max",
+            10,
+            metrics.peak_in_flight
+        );
+        crate::assert_with_log!(
+            metrics.total_rejected == 1,
+            "one rejection",
+            1,
+            metrics.total_rejected
+        );
+        crate::test_complete!("limit_respects_bounds");
+    }
+
+    #[test]
+    fn dropped_permits_release_without_adjusting() {
+        init_test("dropped_permits_release_without_adjusting");
+        let limiter = limiter(
+            AdaptiveLimitConfig::default().initial_limit(4),
+            Aimd::default(),
+        );
+        for _ in 0..10 {
+            let permits: Vec<_> = (0..4)
+                .map(|_| limiter.try_acquire(Time::ZERO).expect("admit"))
+                .collect();
+            drop(permits);
+        }
+        let metrics = limiter.metrics();
+        crate::assert_with_log!(
+            metrics.in_flight == 0,
+            "no leaked permits",
+            0,
+            metrics.in_flight
+        );
+        crate::assert_with_log!(
+            metrics.total_ignored == 40,
+            "drops counted",
+            40,
+            metrics.total_ignored
+        );
+        crate::assert_with_log!(limiter.limit() == 4, "limit untouched", 4, limiter.limit());
+        crate::test_complete!("dropped_permits_release_without_adjusting");
+    }
+
+    #[test]
+    fn adjustments_are_observable() {
+        init_test("adjustments_are_observable");
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let limiter = limiter(
+            AdaptiveLimitConfig::default()
+                .initial_limit(2)
+                .on_adjust(Arc::new(move |adjustment| sink.lock().push(*adjustment))),
+            Aimd::default(),
+        );
+        let permit = limiter.try_acquire(Time::from_millis(10)).expect("admit");
+        permit.success(Time::from_millis(15));
+        let permit = limiter.try_acquire(Time::from_millis(20)).expect("admit");
+        permit.failure(Time::from_millis(30));
+
+        let adjustments = limiter.adjustments();
+        crate::assert_with_log!(
+            adjustments.len() == 2,
+            "two adjustments",
+            2,
+            adjustments.len()
+        );
+        crate::assert_with_log!(
+            (adjustments[0].previous, adjustments[0].limit) == (2, 3),
+            "increase recorded",
+            (2, 3),
+            (adjustments[0].previous, adjustments[0].limit)
+        );
+        crate::assert_with_log!(
+            adjustments[1].sample.latency == Duration::from_millis(10),
+            "latency from virtual times",
+            Duration::from_millis(10),
+            adjustments[1].sample.latency
+        );
+        let seen = seen.lock().clone();
+        crate::assert_with_log!(
+            seen == adjustments,
+            "callback sees same events",
+            adjustments.len(),
+            seen.len()
+        );
+        let metrics = limiter.metrics();
+        crate::assert_with_log!(
+            (metrics.increases, metrics.decreases) == (1, 1),
+            "counters",
+            (1, 1),
+            (metrics.increases, metrics.decreases)
+        );
+        crate::test_complete!("adjustments_are_observable");
+    }
+}
@@ -0,0 +1,643 @@
+//! Incremental harvesting of a dynamically growing group of child tasks.
+//!
+//! [`CompletionSet`] covers the case between [`JoinSet::join_all`] (wait for
+//! everything) and a race (keep only the winner): children are spawned one at
+//! a time as work arrives, and their results are harvested as they finish.
+//!
+//! ```no_run
+//! use asupersync::combinator::CompletionSet;
+//! use asupersync::{main, prelude::*};
+//!
+//! #[main]
+//! async fn main(cx: &Cx) {
+//!     let set = CompletionSet::in_cx(cx);
+//!     for fragment in 0..4_u32 {
+//!         set.spawn_labeled(cx, format!("fragment-{fragment}"), move |_| async move {
+//!             Ok::<_, ()>(fragment * 2)
+//!         })
+//!         .expect("spawn");
+//!     }
+//!     while let Some(done) = set.next(cx).await {
+//!         println!("{:?} -> {:?}", done.label, done.outcome);
+//!     }
+//! }
+//! ```
+//!
+//! Children are real tasks in the owning scope's region, spawned through
+//! [`Cx::spawn_in`] exactly like [`JoinSet`] members. What differs is how
+//! results come back: each child records itself on a finished queue when its
+//! future is dropped (on return, cancellation or panic alike), and
+//! [`CompletionSet::next`] hands children out in that order instead of
+//! scanning every handle. Under the lab runtime the order therefore follows
+//! the seeded schedule and is reproducible per seed.
+//!
+//! Spawning and harvesting both take `&self`, so a set can be shared (for
+//! example in an [`Arc`]) between a task that admits work and one that
+//! collects it. One harvester at a time is expected; a second concurrent
+//! `next` call only receives wakeups once the first has returned.
+//!
+//! [`CompletionSet::close`] requests cancellation of every child still
+//! outstanding and waits for all of them, returning their outcomes, so the
+//! region sees them drained before close completes. Dropping the set only
+//! requests the cancellation; region close remains the quiescence backstop.
+//!
+//! [`JoinSet`]: super::JoinSet
+//! [`JoinSet::join_all`]: super::JoinSet::join_all
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+
+use parking_lot::Mutex;
+
+use super::join_set::{JoinSummary, clone_scope, join_to_outcome};
+use crate::cx::{Cx, Scope};
+use crate::runtime::TaskHandle;
+use crate::runtime::state::SpawnError;
+use crate::types::policy::FailFast;
+use crate::types::{CancelReason, Outcome, Policy, TaskId};
+
+/// A child harvested from a [`CompletionSet`].
+#[derive(Debug)]
+pub struct Completed<T, E> {
+    /// Position of the child in spawn order, starting at zero.
+    pub index: u64,
+    /// The child's task.
+    pub task_id: TaskId,
+    /// Label given at spawn, if any.
+    pub label: Option<String>,
+    /// How the child ended.
+    pub outcome: Outcome<T, E>,
+}
+
+struct Member<T, E> {
+    handle: TaskHandle<Result<T, E>>,
+    label: Option<String>,
+}
+
+struct State<T, E> {
+    /// Children not yet harvested, by spawn index.
+    members: BTreeMap<u64, Member<T, E>>,
+    /// Spawn indices in the order their futures finished.
+    finished: VecDeque<u64>,
+    /// The harvester parked in `next`.
+    waker: Option<Waker>,
+    next_index: u64,
+    summary: JoinSummary,
+}
+
+/// Queues its child as finished when the child's future is dropped.
+struct FinishGuard<T, E> {
+    state: Weak<Mutex<State<T, E>>>,
+    index: u64,
+}
+
+impl<T, E> Drop for FinishGuard<T, E> {
+    fn drop(&mut self) {
+        let Some(state) = self.state.upgrade() else {
+            return;
+        };
+        let waker = {
+            let mut state = state.lock();
+            state.finished.push_back(self.index);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A group of child tasks whose outcomes are harvested in completion order
+/// while new children keep being spawned.
+///
+/// See the [module documentation](self) for ordering and cancellation.
+pub struct CompletionSet<'scope, T, E, P>
+where
+    P: Policy,
+{
+    scope: Scope<'scope, P>,
+    state: Arc<Mutex<State<T, E>>>,
+}
+
+impl<'scope, T, E, P> CompletionSet<'scope, T, E, P>
+where
+    P: Policy,
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    /// Creates an empty set whose children will be spawned into `scope`'s
+    /// region.
+    #[must_use]
+    pub fn new(scope: &'scope Scope<'scope, P>) -> Self {
+        Self::with_scope(clone_scope(scope))
+    }
+
+    fn with_scope(scope: Scope<'scope, P>) -> Self {
+        Self {
+            scope,
+            state: Arc::new(Mutex::new(State {
+                members: BTreeMap::new(),
+                finished: VecDeque::new(),
+                waker: None,
+                next_index: 0,
+                summary: JoinSummary::default(),
+            })),
+        }
+    }
+
+    /// Spawns an unlabeled child into the set's region.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same [`SpawnError`] variants as [`Cx::spawn_in`].
+    /// Admission-time denials are not errors here; they are harvested as
+    /// [`Outcome::Cancelled`].
+    pub fn spawn<F, Fut>(&self, cx: &Cx, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce(Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.spawn_member(cx, None, f)
+    }
+
+    /// Spawns a child carrying `label`, which is handed back with its
+    /// outcome.
+    ///
+    /// # Errors
+    ///
+    /// As for [`spawn`](Self::spawn).
+    pub fn spawn_labeled<F, Fut>(
+        &self,
+        cx: &Cx,
+        label: impl Into<String>,
+        f: F,
+    ) -> Result<(), SpawnError>
+    where
+        F: FnOnce(Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.spawn_member(cx, Some(label.into()), f)
+    }
+
+    /// Number of children spawned and not yet harvested.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.lock().members.len()
+    }
+
+    /// Returns `true` when no child is outstanding.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().members.is_empty()
+    }
+
+    /// Severity summary of the children harvested so far.
+    #[must_use]
+    pub fn summary(&self) -> JoinSummary {
+        self.state.lock().summary
+    }
+
+    /// Waits for the next child to finish and returns it, or `None` once no
+    /// child is outstanding.
+    ///
+    /// Children are returned in the order they finished. Dropping the future
+    /// harvests nothing, so `next` can sit in a `select` next to the source
+    /// of new work.
+    pub async fn next(&self, cx: &Cx) -> Option<Completed<T, E>> {
+        std::future::poll_fn(|task_cx| self.poll_next(cx, task_cx)).await
+    }
+
+    /// Requests cancellation of every outstanding child and waits for all of
+    /// them, returning their outcomes in the order they finished.
+    ///
+    /// Children that finished before the request are returned with their own
+    /// outcome; the rest are harvested as [`Outcome::Cancelled`] once they
+    /// have acknowledged the cancellation.
+    pub async fn close(self, cx: &Cx) -> Vec<Completed<T, E>> {
+        {
+            let state = self.state.lock();
+            let reason = CancelReason::user("completion set closed");
+            for member in state.members.values() {
+                member.handle.abort_with_reason(reason.clone());
+            }
+        }
+        let mut drained = Vec::new();
+        while let Some(completed) = self.next(cx).await {
+            drained.push(completed);
+        }
+        drained
+    }
+
+    fn spawn_member<F, Fut>(&self, cx: &Cx, label: Option<String>, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce(Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let index = {
+            let mut state = self.state.lock();
+            let index = state.next_index;
+            state.next_index += 1;
+            index
+        };
+        let guard = FinishGuard {
+            state: Arc::downgrade(&self.state),
+            index,
+        };
+        // The lock is not held across the spawn: a refused spawn drops the
+        // guard, which takes the lock to queue itself.
+        let spawned = cx.spawn_in(&self.scope, move |child| {
+            let fut = f(child);
+            async move {
+                let _finished = guard;
+                fut.await
+            }
+        });
+
+        let mut state = self.state.lock();
+        match spawned {
+            Ok(handle) => {
+                self.trace_member_spawn(cx, index, label.as_deref(), state.members.len());
+                state.members.insert(index, Member { handle, label });
+                // A child can finish before it is registered; the harvester
+                // waits for the registration in that case.
+                let waker = if state.finished.contains(&index) {
+                    state.waker.take()
+                } else {
+                    None
+                };
+                drop(state);
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+                Ok(())
+            }
+            Err(error) => {
+                state.finished.retain(|&finished| finished != index);
+                Err(error)
+            }
+        }
+    }
+
+    fn poll_next(&self, cx: &Cx, task_cx: &mut Context<'_>) -> Poll<Option<Completed<T, E>>> {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        let Some(&index) = state.finished.front() else {
+            if state.members.is_empty() {
+                return Poll::Ready(None);
+            }
+            state.waker = Some(task_cx.waker().clone());
+            return Poll::Pending;
+        };
+        let Some(member) = state.members.get_mut(&index) else {
+            state.waker = Some(task_cx.waker().clone());
+            return Poll::Pending;
+        };
+
+        // The child's future is gone; its result is at most a hand-off away.
+        let mut join = member.handle.join(cx);
+        let joined = match Pin::new(&mut join).poll(task_cx) {
+            Poll::Ready(joined) => joined,
+            Poll::Pending => {
+                join.defuse_drop_abort();
+                return Poll::Pending;
+            }
+        };
+        drop(join);
+
+        state.finished.pop_front();
+        let member = state.members.remove(&index).expect("member checked above");
+        let outcome = join_to_outcome(joined);
+        state.summary.record(&outcome);
+        Poll::Ready(Some(Completed {
+            index,
+            task_id: member.handle.task_id(),
+            label: member.label,
+            outcome,
+        }))
+    }
+
+    fn trace_member_spawn(&self, cx: &Cx, index: u64, label: Option<&str>, outstanding: usize) {
+        let index = index.to_string();
+        let region = self.scope.region_id().to_string();
+        let outstanding = (outstanding + 1).to_string();
+        cx.trace_with_fields(
+            "completion_set.spawn",
+            &[
+                ("member_index", index.as_str()),
+                ("label", label.unwrap_or("")),
+                ("region", region.as_str()),
+                ("outstanding", outstanding.as_str()),
+            ],
+        );
+    }
+}
+
+impl<T, E> CompletionSet<'static, T, E, FailFast>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    /// Creates an empty set bound to `cx`'s current region.
+    #[must_use]
+    pub fn in_cx(cx: &Cx) -> Self {
+        Self::with_scope(cx.scope())
+    }
+}
+
+impl<T, E, P> Drop for CompletionSet<'_, T, E, P>
+where
+    P: Policy,
+{
+    fn drop(&mut self) {
+        // A cancellation request only; region close waits for the children.
+        // Their finish guards hold a weak reference and find nothing to
+        // update once the set is gone.
+        for member in self.state.lock().members.values() {
+            member
+                .handle
+                .abort_with_reason(CancelReason::user("completion set dropped"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lab::run_async_under_lab;
+    use crate::runtime::yield_now;
+    use crate::types::Severity;
+    use std::collections::BTreeSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    async fn yield_times(times: usize) {
+        for _ in 0..times {
+            yield_now().await;
+        }
+    }
+
+    /// Runs until cancelled, like a member waiting on a slow dependency.
+    async fn until_cancelled(cx: Cx) -> Result<u32, crate::error::Error> {
+        loop {
+            cx.checkpoint()?;
+            yield_now().await;
+        }
+    }
+
+    fn labels<T, E>(completed: &[Completed<T, E>]) -> Vec<String> {
+        completed
+            .iter()
+            .map(|done| done.label.clone().expect("labeled member"))
+            .collect()
+    }
+
+    #[test]
+    fn interleaved_spawn_and_harvest() {
+        init_test("interleaved_spawn_and_harvest");
+        let ((lens, harvested), report) = run_async_under_lab(3, |cx| async move {
+            let set = CompletionSet::<u32, (), _>::in_cx(&cx);
+            let mut lens = Vec::new();
+            let mut harvested = Vec::new();
+            for (label, spins) in [("a", 1), ("b", 6)] {
+                set.spawn_labeled(&cx, label, move |_| async move {
+                    yield_times(spins).await;
+                    Ok(spins as u32)
+                })
+                .expect("spawn");
+            }
+            lens.push(set.len());
+            harvested.push(set.next(&cx).await.expect("first child"));
+            lens.push(set.len());
+            for (label, spins) in [("c", 2), ("d", 0)] {
+                set.spawn_labeled(&cx, label, move |_| async move {
+                    yield_times(spins).await;
+                    Ok(spins as u32)
+                })
+                .expect("spawn");
+            }
+            lens.push(set.len());
+            while let Some(done) = set.next(&cx).await {
+                harvested.push(done);
+            }
+            lens.push(set.len());
+            assert!(set.next(&cx).await.is_none());
+            (lens, harvested)
+        });
+
+        crate::assert_with_log!(lens == [2, 1, 3, 0], "len tracks outstanding", [2, 1, 3, 0], lens);
+        let seen: BTreeSet<String> = labels(&harvested).into_iter().collect();
+        crate::assert_with_log!(
+            harvested.len() == 4 && seen.len() == 4,
+            "every child harvested exactly once",
+            4,
+            &seen
+        );
+        crate::assert_with_log!(report.quiescent, "region drained", true, report.quiescent);
+        crate::test_complete!("interleaved_spawn_and_harvest");
+    }
+
+    #[test]
+    fn spawning_while_harvesting_from_another_task() {
+        init_test("spawning_while_harvesting_from_another_task");
+        let (harvested, _) = run_async_under_lab(5, |cx| async move {
+            let set = Arc::new(CompletionSet::<u32, (), _>::in_cx(&cx));
+            set.spawn_labeled(&cx, "anchor", |_| async move {
+                yield_times(40).await;
+                Ok(0)
+            })
+            .expect("spawn anchor");
+
+            let feeder_set = Arc::clone(&set);
+            cx.spawn(move |feeder_cx| async move {
+                for value in 1..=3_u32 {
+                    yield_now().await;
+                    feeder_set
+                        .spawn_labeled(&feeder_cx, format!("fed-{value}"), move |_| async move {
+                            Ok(value)
+                        })
+                        .expect("spawn fed child");
+                }
+            })
+            .expect("spawn feeder");
+
+            let mut harvested = Vec::new();
+            while harvested.len() < 4 {
+                harvested.push(set.next(&cx).await.expect("child outstanding"));
+            }
+            harvested
+        });
+
+        let order = labels(&harvested);
+        crate::assert_with_log!(
+            order.last().map(String::as_str) == Some("anchor"),
+            "fed children harvested while the anchor runs",
+            "anchor last",
+            &order
+        );
+        crate::test_complete!("spawning_while_harvesting_from_another_task");
+    }
+
+    #[test]
+    fn outcomes_are_attributed_to_their_child() {
+        init_test("outcomes_are_attributed_to_their_child");
+        let (harvested, _) = run_async_under_lab(11, |cx| async move {
+            let set = CompletionSet::<u32, u32, _>::in_cx(&cx);
+            for value in 0..6_u32 {
+                set.spawn_labeled(&cx, value.to_string(), move |_| async move {
+                    yield_times((value as usize * 5) % 4).await;
+                    if value % 2 == 0 { Ok(value) } else { Err(value) }
+                })
+                .expect("spawn");
+            }
+            let mut harvested = Vec::new();
+            while let Some(done) = set.next(&cx).await {
+                harvested.push(done);
+            }
+            harvested
+        });
+
+        let mut task_ids = BTreeSet::new();
+        for done in &harvested {
+            let value: u32 = done.label.as_deref().expect("label").parse().expect("number");
+            crate::assert_with_log!(
+                u64::from(value) == done.index,
+                "index is spawn position",
+                value,
+                done.index
+            );
+            let attributed = match &done.outcome {
+                Outcome::Ok(got) => value % 2 == 0 && *got == value,
+                Outcome::Err(got) => value % 2 == 1 && *got == value,
+                _ => false,
+            };
+            crate::assert_with_log!(attributed, "outcome matches child", value, &done.outcome);
+            task_ids.insert(done.task_id);
+        }
+        crate::assert_with_log!(task_ids.len() == 6, "distinct tasks", 6, task_ids.len());
+        crate::test_complete!("outcomes_are_attributed_to_their_child");
+    }
+
+    #[test]
+    fn completion_order_is_deterministic_per_seed() {
+        init_test("completion_order_is_deterministic_per_seed");
+        let run = |seed: u64| {
+            run_async_under_lab(seed, |cx| async move {
+                let set = CompletionSet::<(), (), _>::in_cx(&cx);
+                for child in 0..8_usize {
+                    set.spawn_labeled(&cx, child.to_string(), move |_| async move {
+                        yield_times((child * 7) % 5).await;
+                        Ok(())
+                    })
+                    .expect("spawn");
+                }
+                let mut order = Vec::new();
+                while let Some(done) = set.next(&cx).await {
+                    order.push(done.label.expect("label"));
+                }
+                order
+            })
+            .0
+        };
+
+        let first = run(42);
+        crate::assert_with_log!(first.len() == 8, "all harvested", 8, first.len());
+        for _ in 0..3 {
+            let again = run(42);
+            crate::assert_with_log!(again == first, "same seed, same order", &first, again);
+        }
+        crate::test_complete!("completion_order_is_deterministic_per_seed");
+    }
+
+    #[test]
+    fn close_cancels_and_drains_the_remainder() {
+        init_test("close_cancels_and_drains_the_remainder");
+        let ((early, drained, summary), report) = run_async_under_lab(17, |cx| async move {
+            let set = CompletionSet::<u32, crate::error::Error, _>::in_cx(&cx);
+            set.spawn_labeled(&cx, "quick", |_| async move { Ok(1) })
+                .expect("spawn quick");
+            for slow in 0..3 {
+                set.spawn_labeled(&cx, format!("slow-{slow}"), until_cancelled)
+                    .expect("spawn slow");
+            }
+            let early = set.next(&cx).await.expect("quick child");
+            let summary = set.summary();
+            (early, set.close(&cx).await, summary)
+        });
+
+        crate::assert_with_log!(
+            early.label.as_deref() == Some("quick") && matches!(early.outcome, Outcome::Ok(1)),
+            "quick child harvested before close",
+            "quick",
+            &early.label
+        );
+        crate::assert_with_log!(
+            summary.completed() == 1 && summary.worst() == Severity::Ok,
+            "summary counts the harvest",
+            1,
+            summary.completed()
+        );
+        crate::assert_with_log!(drained.len() == 3, "close drains every child", 3, drained.len());
+        for done in &drained {
+            crate::assert_with_log!(
+                matches!(done.outcome, Outcome::Cancelled(_)),
+                "remainder cancelled",
+                "Cancelled",
+                &done.outcome
+            );
+        }
+        crate::assert_with_log!(report.quiescent, "region quiescent", true, report.quiescent);
+        crate::test_complete!("close_cancels_and_drains_the_remainder");
+    }
+
+    struct DropCount(Arc<AtomicUsize>);
+
+    impl Drop for DropCount {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn dropping_the_set_leaks_nothing() {
+        init_test("dropping_the_set_leaks_nothing");
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&dropped);
+        let (state, report) = run_async_under_lab(23, |cx| async move {
+            let set = CompletionSet::<u32, crate::error::Error, _>::in_cx(&cx);
+            for _ in 0..3 {
+                let owned = DropCount(Arc::clone(&counter));
+                set.spawn(&cx, move |child| async move {
+                    let _owned = owned;
+                    until_cancelled(child).await
+                })
+                .expect("spawn");
+            }
+            yield_times(4).await;
+            let state = Arc::downgrade(&set.state);
+            drop(set);
+            state
+        });
+
+        crate::assert_with_log!(
+            dropped.load(Ordering::SeqCst) == 3,
+            "every child future dropped",
+            3,
+            dropped.load(Ordering::SeqCst)
+        );
+        crate::assert_with_log!(
+            state.upgrade().is_none(),
+            "set state freed",
+            true,
+            state.upgrade().is_none()
+        );
+        crate::assert_with_log!(report.quiescent, "region quiescent", true, report.quiescent);
+        crate::test_complete!("dropping_the_set_leaks_nothing");
+    }
+}
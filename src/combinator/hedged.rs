@@ -0,0 +1,874 @@
+//! Budgeted multi-attempt request hedging with loser draining.
+//!
+//! [`hedged`] runs one request as a series of attempts: the primary starts
+//! immediately and, while nothing has completed, a new attempt (a hedge) starts
+//! every hedge delay up to [`HedgePolicy::with_max_hedges`]. The first attempt
+//! to succeed wins.
+//!
+//! Unlike [`hedge`](super::hedge::hedge), losers are not just dropped. Each
+//! attempt receives an [`AttemptCancel`] signal; once a winner is known every
+//! other in-flight attempt is signalled and polled until it finishes, so it can
+//! resolve its obligations and return or close its connection before the
+//! hedged future completes. Attempts must therefore observe their signal.
+//!
+//! # Guard rails
+//!
+//! - Only requests marked [`Idempotency::Idempotent`] are ever hedged; other
+//!   requests run as a single attempt.
+//! - A shared [`HedgeBudget`] caps hedges to a fraction of all requests, so a
+//!   slow backend does not receive twice the load exactly when it is slowest.
+//! - With [`HedgePolicy::with_endpoints`] every attempt holds a lease on an
+//!   endpoint from an [`EndpointSet`], and hedges avoid the endpoints earlier
+//!   attempts of the same request went to whenever another one is available.
+//!
+//! The hedge delay is either static or the tracked latency quantile of an
+//! [`AdaptiveHedgePolicy`], which is fed each request's completed latency.
+//!
+//! ```ignore
+//! let policy = HedgePolicy::new(HedgeDelay::Static(Duration::from_millis(20)))
+//!     .with_budget(Arc::new(HedgeBudget::new(0.05, 10)))
+//!     .with_endpoints(replicas.clone());
+//! let result = hedged(&policy, Idempotency::Idempotent, |attempt| {
+//!     let lease = attempt.take_lease();
+//!     lookup(lease, key.clone(), attempt.cancel().clone())
+//! })
+//! .await;
+//! metrics.record(result.winner, result.hedge_fired());
+//! ```
+
+use super::hedge::AdaptiveHedgePolicy;
+use crate::net::endpoint_set::{EndpointLease, EndpointSet};
+use crate::time::{Sleep, wall_now};
+use crate::types::cancel::CancelReason;
+use crate::types::{Outcome, Time};
+use parking_lot::Mutex;
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+const PPM: u64 = 1_000_000;
+
+fn duration_to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Whether a request may safely be sent more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idempotency {
+    /// Duplicate executions are harmless; the request may be hedged.
+    Idempotent,
+    /// The request must run at most once and is never hedged.
+    NonIdempotent,
+}
+
+/// How long to wait before each hedge.
+#[derive(Debug, Clone)]
+pub enum HedgeDelay {
+    /// A fixed delay.
+    Static(Duration),
+    /// The tracker's current latency quantile; completed requests are recorded.
+    Percentile(Arc<Mutex<AdaptiveHedgePolicy>>),
+}
+
+impl HedgeDelay {
+    fn current(&self) -> Duration {
+        match self {
+            Self::Static(delay) => *delay,
+            Self::Percentile(tracker) => tracker.lock().next_hedge_delay(),
+        }
+    }
+
+    fn record(&self, latency: Duration) {
+        if let Self::Percentile(tracker) = self {
+            tracker.lock().record(latency);
+        }
+    }
+}
+
+/// Caps hedges to a fraction of requests, shared by every request it covers.
+///
+/// A hedge is allowed while `hedges + 1 <= ratio * requests + burst`.
+#[derive(Debug)]
+pub struct HedgeBudget {
+    ratio_ppm: u64,
+    burst_ppm: u64,
+    requests: AtomicU64,
+    hedges: AtomicU64,
+}
+
+impl HedgeBudget {
+    /// Creates a budget allowing `ratio` (clamped to `0.0..=1.0`) hedges per
+    /// request plus `burst` hedges up front.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+    pub fn new(ratio: f64, burst: u32) -> Self {
+        let ratio = if ratio.is_nan() {
+            0.0
+        } else {
+            ratio.clamp(0.0, 1.0)
+        };
+        Self {
+            ratio_ppm: (ratio * PPM as f64).round() as u64,
+            burst_ppm: u64::from(burst) * PPM,
+            requests: AtomicU64::new(0),
+            hedges: AtomicU64::new(0),
+        }
+    }
+
+    /// Requests counted against this budget.
+    #[must_use]
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Acquire)
+    }
+
+    /// Hedges granted by this budget.
+    #[must_use]
+    pub fn hedges(&self) -> u64 {
+        self.hedges.load(Ordering::Acquire)
+    }
+
+    fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn try_acquire(&self) -> bool {
+        let allowance = self
+            .requests()
+            .saturating_mul(self.ratio_ppm)
+            .saturating_add(self.burst_ppm);
+        self.hedges
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |hedges| {
+                let next = hedges + 1;
+                (next.saturating_mul(PPM) <= allowance).then_some(next)
+            })
+            .is_ok()
+    }
+}
+
+/// Hedging policy shared by the requests it applies to.
+#[derive(Debug, Clone)]
+pub struct HedgePolicy {
+    delay: HedgeDelay,
+    max_hedges: u32,
+    budget: Option<Arc<HedgeBudget>>,
+    endpoints: Option<EndpointSet>,
+    time_getter: fn() -> Time,
+}
+
+impl HedgePolicy {
+    /// Creates a policy with one hedge per request and no budget.
+    #[must_use]
+    pub fn new(delay: HedgeDelay) -> Self {
+        Self {
+            delay,
+            max_hedges: 1,
+            budget: None,
+            endpoints: None,
+            time_getter: wall_now,
+        }
+    }
+
+    /// Sets the maximum hedges per request (0 disables hedging).
+    #[must_use]
+    pub fn with_max_hedges(mut self, max_hedges: u32) -> Self {
+        self.max_hedges = max_hedges;
+        self
+    }
+
+    /// Shares `budget` across every request hedged under this policy.
+    #[must_use]
+    pub fn with_budget(mut self, budget: Arc<HedgeBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Leases an endpoint from `endpoints` for every attempt.
+    #[must_use]
+    pub fn with_endpoints(mut self, endpoints: EndpointSet) -> Self {
+        self.endpoints = Some(endpoints);
+        self
+    }
+
+    /// Sets the time source used for hedge deadlines and latency.
+    #[must_use]
+    pub fn with_time_getter(mut self, time_getter: fn() -> Time) -> Self {
+        self.time_getter = time_getter;
+        self
+    }
+
+    /// Returns the budget, if any.
+    #[must_use]
+    pub fn budget(&self) -> Option<&Arc<HedgeBudget>> {
+        self.budget.as_ref()
+    }
+}
+
+#[derive(Debug, Default)]
+struct CancelSignal {
+    cancelled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Cancellation signal handed to each attempt.
+///
+/// Set when another attempt won; the attempt should then release what it
+/// holds and finish promptly (its outcome is discarded).
+#[derive(Debug, Clone, Default)]
+pub struct AttemptCancel {
+    signal: Arc<CancelSignal>,
+}
+
+impl AttemptCancel {
+    /// Returns true once another attempt has won.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.signal.cancelled.load(Ordering::Acquire)
+    }
+
+    /// The cancellation reason, once cancelled.
+    #[must_use]
+    pub fn reason(&self) -> Option<CancelReason> {
+        self.is_cancelled().then(CancelReason::race_loser)
+    }
+
+    /// Polls for cancellation, registering `cx`'s waker while not cancelled.
+    pub fn poll_cancelled(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_cancelled() {
+            return Poll::Ready(());
+        }
+        *self.signal.waker.lock() = Some(cx.waker().clone());
+        // Re-check after registering so a concurrent cancel is not missed.
+        if self.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn cancel(&self) {
+        self.signal.cancelled.store(true, Ordering::Release);
+        let waker = self.signal.waker.lock().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// One attempt of a hedged request.
+#[derive(Debug)]
+pub struct HedgeAttempt {
+    index: u32,
+    cancel: AttemptCancel,
+    lease: Option<EndpointLease>,
+}
+
+impl HedgeAttempt {
+    /// Attempt number; `0` is the primary.
+    #[must_use]
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns true for hedges (every attempt but the primary).
+    #[must_use]
+    pub const fn is_hedge(&self) -> bool {
+        self.index > 0
+    }
+
+    /// The signal raised when another attempt wins.
+    #[must_use]
+    pub fn cancel(&self) -> &AttemptCancel {
+        &self.cancel
+    }
+
+    /// The leased endpoint, when the policy balances across endpoints.
+    #[must_use]
+    pub fn endpoint(&self) -> Option<SocketAddr> {
+        self.lease.as_ref().map(EndpointLease::addr)
+    }
+
+    /// Takes the endpoint lease so the request can resolve it with its outcome.
+    ///
+    /// A lease dropped unresolved (for example by a cancelled loser) records
+    /// no outcome against the endpoint.
+    pub fn take_lease(&mut self) -> Option<EndpointLease> {
+        self.lease.take()
+    }
+}
+
+/// Why a request ran with fewer hedges than the policy allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HedgeSuppression {
+    /// The request was not marked idempotent.
+    NotIdempotent,
+    /// The hedge budget was exhausted when a hedge was due.
+    Budget,
+}
+
+/// Outcome of a hedged request with attribution.
+#[derive(Debug)]
+pub struct Hedged<T, E> {
+    /// The winning attempt's outcome, or the last failure if none succeeded.
+    pub outcome: Outcome<T, E>,
+    /// Index of the attempt that produced `outcome` (`0` is the primary).
+    pub winner: u32,
+    /// Attempts started, including the primary.
+    pub attempts: u32,
+    /// Losers cancelled and drained after the winner completed.
+    pub drained: u32,
+    /// Why hedging was held back, if it was.
+    pub suppressed: Option<HedgeSuppression>,
+    /// Time from the start of the request until `outcome` was produced.
+    pub latency: Duration,
+}
+
+impl<T, E> Hedged<T, E> {
+    /// Returns true if at least one hedge was started.
+    #[must_use]
+    pub const fn hedge_fired(&self) -> bool {
+        self.attempts > 1
+    }
+
+    /// Returns true if a hedge produced the outcome.
+    #[must_use]
+    pub const fn hedge_won(&self) -> bool {
+        self.winner > 0
+    }
+}
+
+struct Attempt<Fut> {
+    future: Option<Pin<Box<Fut>>>,
+    cancel: AttemptCancel,
+}
+
+/// Future returned by [`hedged`].
+pub struct HedgedFuture<F, Fut, T, E> {
+    make_request: F,
+    policy: HedgePolicy,
+    delay: Duration,
+    attempts: Vec<Attempt<Fut>>,
+    used_endpoints: Vec<SocketAddr>,
+    sleep: Option<Sleep>,
+    started: Time,
+    suppressed: Option<HedgeSuppression>,
+    winner: Option<(u32, Outcome<T, E>, Time)>,
+    last_failure: Option<(u32, Outcome<T, E>, Time)>,
+    drained: u32,
+    completed: bool,
+}
+
+impl<F, Fut, T, E> fmt::Debug for HedgedFuture<F, Fut, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HedgedFuture")
+            .field("delay", &self.delay)
+            .field("attempts", &self.attempts.len())
+            .field("suppressed", &self.suppressed)
+            .field("completed", &self.completed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut, T, E> HedgedFuture<F, Fut, T, E>
+where
+    F: FnMut(HedgeAttempt) -> Fut,
+{
+    fn launch(&mut self) {
+        let index = u32::try_from(self.attempts.len()).unwrap_or(u32::MAX);
+        let lease = self.policy.endpoints.as_ref().and_then(|endpoints| {
+            endpoints
+                .pick(&self.used_endpoints)
+                .or_else(|| endpoints.pick(&[]))
+        });
+        if let Some(lease) = &lease {
+            self.used_endpoints.push(lease.addr());
+        }
+        let cancel = AttemptCancel::default();
+        let future = (self.make_request)(HedgeAttempt {
+            index,
+            cancel: cancel.clone(),
+            lease,
+        });
+        self.attempts.push(Attempt {
+            future: Some(Box::pin(future)),
+            cancel,
+        });
+    }
+
+    fn hedges_launched(&self) -> u32 {
+        u32::try_from(self.attempts.len().saturating_sub(1)).unwrap_or(u32::MAX)
+    }
+
+    fn finish(&mut self, index: u32, outcome: Outcome<T, E>, at: Time) -> Hedged<T, E> {
+        self.completed = true;
+        self.sleep = None;
+        let latency = Duration::from_nanos(at.duration_since(self.started));
+        if outcome.is_ok() {
+            self.policy.delay.record(latency);
+        }
+        Hedged {
+            outcome,
+            winner: index,
+            attempts: u32::try_from(self.attempts.len()).unwrap_or(u32::MAX),
+            drained: self.drained,
+            suppressed: self.suppressed,
+            latency,
+        }
+    }
+}
+
+impl<F, Fut, T, E> Future for HedgedFuture<F, Fut, T, E>
+where
+    F: FnMut(HedgeAttempt) -> Fut + Unpin,
+    Fut: Future<Output = Outcome<T, E>>,
+    T: Unpin,
+    E: Unpin,
+{
+    type Output = Hedged<T, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        assert!(!this.completed, "HedgedFuture polled after completion");
+
+        loop {
+            let now = (this.policy.time_getter)();
+            let mut in_flight = 0;
+            let mut decided = false;
+            for (index, attempt) in (0_u32..).zip(this.attempts.iter_mut()) {
+                let Some(future) = attempt.future.as_mut() else {
+                    continue;
+                };
+                let Poll::Ready(outcome) = future.as_mut().poll(cx) else {
+                    in_flight += 1;
+                    continue;
+                };
+                attempt.future = None;
+                if this.winner.is_some() {
+                    this.drained += 1;
+                } else if outcome.is_ok() {
+                    this.winner = Some((index, outcome, now));
+                    decided = true;
+                } else {
+                    this.last_failure = Some((index, outcome, now));
+                }
+            }
+
+            if decided {
+                // Signal every loser, then poll them again so they can drain.
+                for attempt in &this.attempts {
+                    if attempt.future.is_some() {
+                        attempt.cancel.cancel();
+                    }
+                }
+                continue;
+            }
+            if this.winner.is_some() {
+                if in_flight > 0 {
+                    return Poll::Pending;
+                }
+                let (index, outcome, at) = this.winner.take().expect("winner recorded");
+                return Poll::Ready(this.finish(index, outcome, at));
+            }
+            if in_flight == 0 {
+                let (index, outcome, at) = this.last_failure.take().expect("attempt finished");
+                return Poll::Ready(this.finish(index, outcome, at));
+            }
+
+            let Some(sleep) = this.sleep.as_mut() else {
+                return Poll::Pending;
+            };
+            let fired =
+                sleep.poll_with_time(now).is_ready() || Pin::new(&mut *sleep).poll(cx).is_ready();
+            if !fired {
+                return Poll::Pending;
+            }
+            let refused = this
+                .policy
+                .budget
+                .as_ref()
+                .is_some_and(|budget| !budget.try_acquire());
+            if refused {
+                this.suppressed = Some(HedgeSuppression::Budget);
+                this.sleep = None;
+                return Poll::Pending;
+            }
+            this.launch();
+            if this.hedges_launched() < this.policy.max_hedges {
+                let next = now.saturating_add_nanos(duration_to_nanos(this.delay));
+                if let Some(sleep) = this.sleep.as_mut() {
+                    sleep.reset(next);
+                }
+            } else {
+                this.sleep = None;
+            }
+        }
+    }
+}
+
+/// Runs `make_request` as a hedged request under `policy`.
+///
+/// The primary attempt is created immediately; hedges follow every hedge delay
+/// while no attempt has completed, until the policy's hedge limit or budget
+/// stops them. The first
+/// successful attempt wins and the rest are cancelled through their
+/// [`AttemptCancel`] and drained. If every attempt fails, the last failure is
+/// returned.
+pub fn hedged<F, Fut, T, E>(
+    policy: &HedgePolicy,
+    idempotency: Idempotency,
+    make_request: F,
+) -> HedgedFuture<F, Fut, T, E>
+where
+    F: FnMut(HedgeAttempt) -> Fut,
+    Fut: Future<Output = Outcome<T, E>>,
+{
+    let started = (policy.time_getter)();
+    let delay = policy.delay.current();
+    let hedgeable = idempotency == Idempotency::Idempotent;
+    if hedgeable && let Some(budget) = &policy.budget {
+        budget.record_request();
+    }
+    let sleep = (hedgeable && policy.max_hedges > 0).then(|| {
+        let deadline = started.saturating_add_nanos(duration_to_nanos(delay));
+        Sleep::with_time_getter(deadline, policy.time_getter)
+    });
+    let mut future = HedgedFuture {
+        make_request,
+        policy: policy.clone(),
+        delay,
+        attempts: Vec::new(),
+        used_endpoints: Vec::new(),
+        sleep,
+        started,
+        suppressed: (!hedgeable).then_some(HedgeSuppression::NotIdempotent),
+        winner: None,
+        last_failure: None,
+        drained: 0,
+        completed: false,
+    };
+    future.launch();
+    future
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::net::endpoint_set::{BalancePolicy, EndpointSetConfig};
+    use std::sync::atomic::AtomicUsize;
+
+    const MS: u64 = 1_000_000;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    std::thread_local! {
+        static TEST_NOW: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    }
+
+    fn test_time() -> Time {
+        Time::from_nanos(TEST_NOW.with(std::cell::Cell::get))
+    }
+
+    fn set_test_time(t: u64) {
+        TEST_NOW.with(|now| now.set(t));
+    }
+
+    /// Request attempt that succeeds at `ready_at` unless cancelled first.
+    struct Scripted {
+        ready_at: u64,
+        value: u32,
+        cancel: AttemptCancel,
+        lease: Option<EndpointLease>,
+        live: Arc<AtomicUsize>,
+    }
+
+    impl Scripted {
+        fn settle(&mut self) {
+            self.live.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    impl Future for Scripted {
+        type Output = Outcome<u32, &'static str>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.cancel.poll_cancelled(cx).is_ready() {
+                self.settle();
+                self.lease = None;
+                let reason = self.cancel.reason().expect("cancelled");
+                return Poll::Ready(Outcome::Cancelled(reason));
+            }
+            if test_time().as_nanos() >= self.ready_at {
+                self.settle();
+                if let Some(lease) = self.lease.take() {
+                    lease.succeed();
+                }
+                return Poll::Ready(Outcome::Ok(self.value));
+            }
+            Poll::Pending
+        }
+    }
+
+    /// Builds the attempt factory; `latency` maps an attempt to its delay.
+    fn scripted(
+        live: &Arc<AtomicUsize>,
+        latency: impl Fn(&HedgeAttempt) -> u64 + Unpin,
+    ) -> impl FnMut(HedgeAttempt) -> Scripted + Unpin {
+        let live = Arc::clone(live);
+        move |mut attempt| {
+            live.fetch_add(1, Ordering::SeqCst);
+            Scripted {
+                ready_at: test_time().as_nanos() + latency(&attempt),
+                value: attempt.index(),
+                cancel: attempt.cancel().clone(),
+                lease: attempt.take_lease(),
+                live: Arc::clone(&live),
+            }
+        }
+    }
+
+    fn by_index(latencies: &'static [u64]) -> impl Fn(&HedgeAttempt) -> u64 + Unpin {
+        move |attempt| latencies[attempt.index() as usize]
+    }
+
+    fn static_policy(delay_ms: u64) -> HedgePolicy {
+        HedgePolicy::new(HedgeDelay::Static(Duration::from_millis(delay_ms)))
+            .with_time_getter(test_time)
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let mut cx = Context::from_waker(Waker::noop());
+        Pin::new(future).poll(&mut cx)
+    }
+
+    /// Polls, advancing the virtual clock 1ms per pending poll.
+    fn drive<F: Future + Unpin>(mut future: F) -> F::Output {
+        for _ in 0..10_000 {
+            if let Poll::Ready(output) = poll_once(&mut future) {
+                return output;
+            }
+            set_test_time(test_time().as_nanos() + MS);
+        }
+        panic!("hedged request did not finish");
+    }
+
+    #[test]
+    fn hedge_fires_exactly_at_delay_and_drains_loser() {
+        init_test("hedge_fires_exactly_at_delay_and_drains_loser");
+        set_test_time(0);
+        let live = Arc::new(AtomicUsize::new(0));
+        let policy = static_policy(10);
+        let mut future = hedged(
+            &policy,
+            Idempotency::Idempotent,
+            scripted(&live, by_index(&[100 * MS, 5 * MS])),
+        );
+
+        set_test_time(10 * MS - 1);
+        let pending = poll_once(&mut future).is_pending();
+        let started = live.load(Ordering::SeqCst);
+        crate::assert_with_log!(
+            pending && started == 1,
+            "no hedge before the delay",
+            1,
+            started
+        );
+
+        set_test_time(10 * MS);
+        let pending = poll_once(&mut future).is_pending();
+        let started = live.load(Ordering::SeqCst);
+        crate::assert_with_log!(pending && started == 2, "hedge at the delay", 2, started);
+
+        set_test_time(15 * MS);
+        let Poll::Ready(result) = poll_once(&mut future) else {
+            panic!("hedge should have won at 15ms");
+        };
+        crate::assert_with_log!(result.hedge_won(), "hedge won", 1, result.winner);
+        crate::assert_with_log!(
+            matches!(result.outcome, Outcome::Ok(1)),
+            "winner outcome",
+            "Ok(1)",
+            format!("{:?}", result.outcome)
+        );
+        crate::assert_with_log!(result.drained == 1, "loser drained", 1, result.drained);
+        let live = live.load(Ordering::SeqCst);
+        crate::assert_with_log!(live == 0, "no attempt left running", 0, live);
+        crate::assert_with_log!(
+            result.latency == Duration::from_millis(15),
+            "latency",
+            "15ms",
+            result.latency
+        );
+        crate::test_complete!("hedge_fires_exactly_at_delay_and_drains_loser");
+    }
+
+    #[test]
+    fn budget_exhaustion_suppresses_hedges() {
+        init_test("budget_exhaustion_suppresses_hedges");
+        set_test_time(0);
+        let live = Arc::new(AtomicUsize::new(0));
+        let budget = Arc::new(HedgeBudget::new(0.5, 1));
+        let policy = static_policy(10).with_budget(Arc::clone(&budget));
+
+        let mut results = Vec::new();
+        for _ in 0..3 {
+            let future = hedged(
+                &policy,
+                Idempotency::Idempotent,
+                scripted(&live, by_index(&[100 * MS, 5 * MS])),
+            );
+            results.push(drive(future));
+        }
+
+        let fired: Vec<bool> = results.iter().map(Hedged::hedge_fired).collect();
+        crate::assert_with_log!(
+            fired == [true, true, false],
+            "third hedge refused",
+            "[true, true, false]",
+            format!("{fired:?}")
+        );
+        let third = &results[2];
+        crate::assert_with_log!(
+            third.suppressed == Some(HedgeSuppression::Budget),
+            "suppression attributed to the budget",
+            "Some(Budget)",
+            format!("{:?}", third.suppressed)
+        );
+        crate::assert_with_log!(
+            third.winner == 0 && third.latency == Duration::from_millis(100),
+            "primary still completes",
+            "winner 0 after 100ms",
+            format!("winner {} after {:?}", third.winner, third.latency)
+        );
+        crate::assert_with_log!(
+            budget.requests() == 3 && budget.hedges() == 2,
+            "budget counters",
+            "3 requests / 2 hedges",
+            format!("{} requests / {} hedges", budget.requests(), budget.hedges())
+        );
+        crate::test_complete!("budget_exhaustion_suppresses_hedges");
+    }
+
+    #[test]
+    fn attribution_covers_each_way_a_request_completes() {
+        init_test("attribution_covers_each_way_a_request_completes");
+        set_test_time(0);
+        let live = Arc::new(AtomicUsize::new(0));
+        let policy = static_policy(10);
+
+        let fast = drive(hedged(
+            &policy,
+            Idempotency::Idempotent,
+            scripted(&live, by_index(&[5 * MS])),
+        ));
+        crate::assert_with_log!(
+            fast.winner == 0 && !fast.hedge_fired() && fast.suppressed.is_none(),
+            "fast primary needs no hedge",
+            "winner 0, 1 attempt",
+            format!("winner {}, {} attempts", fast.winner, fast.attempts)
+        );
+
+        let primary_late = drive(hedged(
+            &policy,
+            Idempotency::Idempotent,
+            scripted(&live, by_index(&[15 * MS, 50 * MS])),
+        ));
+        crate::assert_with_log!(
+            primary_late.hedge_fired() && !primary_late.hedge_won() && primary_late.drained == 1,
+            "primary wins after the hedge fired",
+            "winner 0, 2 attempts, 1 drained",
+            format!(
+                "winner {}, {} attempts, {} drained",
+                primary_late.winner, primary_late.attempts, primary_late.drained
+            )
+        );
+
+        let unsafe_request = drive(hedged(
+            &policy,
+            Idempotency::NonIdempotent,
+            scripted(&live, by_index(&[100 * MS])),
+        ));
+        crate::assert_with_log!(
+            unsafe_request.attempts == 1
+                && unsafe_request.suppressed == Some(HedgeSuppression::NotIdempotent),
+            "non-idempotent requests are never hedged",
+            "1 attempt, NotIdempotent",
+            format!(
+                "{} attempts, {:?}",
+                unsafe_request.attempts, unsafe_request.suppressed
+            )
+        );
+        let live = live.load(Ordering::SeqCst);
+        crate::assert_with_log!(live == 0, "no attempt left running", 0, live);
+        crate::test_complete!("attribution_covers_each_way_a_request_completes");
+    }
+
+    #[test]
+    fn hedging_bounds_tail_latency_with_one_slow_replica() {
+        init_test("hedging_bounds_tail_latency_with_one_slow_replica");
+        set_test_time(0);
+        let slow: SocketAddr = "10.0.0.1:80".parse().unwrap();
+        let replicas = ["10.0.0.1:80", "10.0.0.2:80", "10.0.0.3:80"]
+            .map(|addr| addr.parse::<SocketAddr>().unwrap());
+        let config = EndpointSetConfig::new(BalancePolicy::RoundRobin);
+        let endpoints = EndpointSet::new(config, replicas);
+        let live = Arc::new(AtomicUsize::new(0));
+        let latency = move |attempt: &HedgeAttempt| {
+            if attempt.endpoint() == Some(slow) {
+                200 * MS
+            } else {
+                10 * MS
+            }
+        };
+
+        let mut tails = Vec::new();
+        for max_hedges in [0, 1] {
+            let policy = static_policy(20)
+                .with_max_hedges(max_hedges)
+                .with_endpoints(endpoints.clone());
+            let mut worst = Duration::ZERO;
+            for _ in 0..30 {
+                let result = drive(hedged(
+                    &policy,
+                    Idempotency::Idempotent,
+                    scripted(&live, latency),
+                ));
+                assert!(result.outcome.is_ok());
+                worst = worst.max(result.latency);
+            }
+            tails.push(worst);
+        }
+
+        crate::assert_with_log!(
+            tails[0] == Duration::from_millis(200),
+            "slow replica dominates the unhedged tail",
+            "200ms",
+            tails[0]
+        );
+        crate::assert_with_log!(
+            tails[1] <= Duration::from_millis(30),
+            "hedges route around the slow replica",
+            "<= 30ms",
+            tails[1]
+        );
+        let leaked: usize = endpoints.metrics().iter().map(|m| m.in_flight).sum();
+        crate::assert_with_log!(leaked == 0, "every lease released", 0, leaked);
+        let live = live.load(Ordering::SeqCst);
+        crate::assert_with_log!(live == 0, "no attempt left running", 0, live);
+        crate::test_complete!("hedging_bounds_tail_latency_with_one_slow_replica");
+    }
+}
@@ -332,7 +332,7 @@ where
 }
 
 /// Folds a task-join result into the four-valued [`Outcome`] lattice.
-fn join_to_outcome<T, E>(joined: Result<Result<T, E>, JoinError>) -> Outcome<T, E> {
+pub(super) fn join_to_outcome<T, E>(joined: Result<Result<T, E>, JoinError>) -> Outcome<T, E> {
     match joined {
         Ok(Ok(value)) => Outcome::Ok(value),
         Ok(Err(error)) => Outcome::Err(error),
@@ -354,7 +354,7 @@ fn try_join_to_outcome<T, E>(
     }
 }
 
-fn clone_scope<'scope, P>(scope: &'scope Scope<'scope, P>) -> Scope<'scope, P>
+pub(super) fn clone_scope<'scope, P>(scope: &'scope Scope<'scope, P>) -> Scope<'scope, P>
 where
     P: Policy,
 {
@@ -391,7 +391,7 @@ impl JoinSummary {
         self.worst
     }
 
-    fn record<T, E>(&mut self, outcome: &Outcome<T, E>) {
+    pub(super) fn record<T, E>(&mut self, outcome: &Outcome<T, E>) {
         self.completed += 1;
         self.worst = self.worst.max(outcome.severity());
     }
@@ -10,13 +10,17 @@
 //! - [`retry`]: Retry with exponential backoff
 //! - [`quorum`]: M-of-N completion semantics for consensus patterns
 //! - [`hedge`]: Latency hedging - start backup after delay, first wins
+//! - [`hedged`](mod@hedged): Budgeted multi-attempt hedging that drains cancelled losers
 //! - [`first_ok`]: Try operations sequentially until one succeeds
 //! - [`pipeline`]: Chain transformations with staged processing
 //! - [`map_reduce`]: Parallel map followed by monoid-based reduction
 //! - [`circuit_breaker`]: Failure detection and prevention
 //! - [`bulkhead`]: Resource isolation and concurrency limiting
 //! - [`rate_limit`]: Throughput control with token bucket algorithm
+//! - [`adaptive_concurrency`]: Latency-driven concurrency limits (AIMD/gradient)
+//! - [`completion_set`]: Harvest dynamically spawned children in completion order
 
+pub mod adaptive_concurrency;
 /// Adaptive latency-hedging controllers.
 pub mod adaptive_hedge;
 #[cfg(test)]
@@ -28,8 +32,10 @@ pub mod bulkhead;
 #[cfg(test)]
 pub mod bulkhead_metamorphic;
 pub mod circuit_breaker;
+pub mod completion_set;
 pub mod first_ok;
 pub mod hedge;
+pub mod hedged;
 pub mod join;
 pub mod join_set;
 pub mod laws;
@@ -48,6 +54,10 @@ pub mod timeout;
 #[cfg(test)]
 pub mod timeout_metamorphic;
 
+pub use adaptive_concurrency::{
+    AdaptiveLimitConfig, AdaptiveLimitMetrics, AdaptiveLimiter, AdaptivePermit, Aimd, Gradient,
+    LimitAdjustment, LimitAlgorithm, LimitOutcome, LimitRejected, LimitSample,
+};
 pub use adaptive_hedge::PeakEwmaHedgeController;
 pub use bracket::{BracketError, bracket, bracket_move, commit_section, try_commit_section};
 pub use bulkhead::{
@@ -59,6 +69,7 @@ pub use circuit_breaker::{
     CircuitBreakerPolicyBuilder, FailurePredicate, Permit, SlidingWindowConfig, State,
     StateChangeCallback,
 };
+pub use completion_set::{Completed, CompletionSet};
 pub use first_ok::{
     FirstOk, FirstOkError, FirstOkFailure, FirstOkResult, FirstOkSuccess, first_ok_outcomes,
     first_ok_to_result,
@@ -67,6 +78,10 @@ pub use hedge::{
     AdaptiveHedgePolicy, Hedge, HedgeConfig, HedgeError, HedgeFuture, HedgeResult, HedgeWinner,
     hedge, hedge_outcomes, hedge_to_result,
 };
+pub use hedged::{
+    AttemptCancel, HedgeAttempt, HedgeBudget, HedgeDelay, HedgePolicy, HedgeSuppression, Hedged,
+    HedgedFuture, Idempotency, hedged,
+};
 pub use join::{
     Join, Join2Result, JoinAll, JoinAllError, JoinAllResult, JoinError, aggregate_outcomes,
     join_all_outcomes, join_all_to_result, join2_outcomes, join2_to_result, make_join_all_result,
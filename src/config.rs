@@ -15,9 +15,11 @@ use crate::http::h1::server::Http1Config;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::http::pool::PoolConfig;
 use crate::observability::{LogLevel, ObservabilityConfig};
+use crate::runtime::scheduler::{DEFAULT_REGION_WEIGHT, RegionFairness};
 use crate::security::{AuthKey, AuthMode, SecurityContext};
 use crate::transport::{
-    AggregatorConfig, ExperimentalTransportGate, PathSelectionPolicy, TransportCodingPolicy,
+    AggregatorConfig, CongestionAlgorithm, CongestionControlConfig, ExperimentalTransportGate,
+    PathId, PathSelectionPolicy, SymbolPacer, TransportCodingPolicy,
 };
 use std::collections::BTreeMap;
 #[cfg(not(target_arch = "wasm32"))]
@@ -65,6 +67,10 @@ impl RaptorQConfig {
             return Err(ConfigError::InsufficientMemory);
         }
 
+        if self.resources.default_region_weight == 0 {
+            return Err(ConfigError::InvalidRegionWeight);
+        }
+
         if self.timeouts.default_timeout < Duration::from_millis(100) {
             return Err(ConfigError::TimeoutTooShort);
         }
@@ -93,6 +99,11 @@ impl RaptorQConfig {
         // live load() / builder paths instead of being a phantom control.
         self.security.validate()?;
 
+        self.transport
+            .congestion
+            .validate()
+            .map_err(ConfigError::InvalidCongestionControl)?;
+
         Ok(())
     }
 }
@@ -309,6 +320,8 @@ pub struct TransportConfig {
     pub experiment_gate: ExperimentalTransportGate,
     /// Preview-coded transport policy request. Defaults to conservative transport only.
     pub coding_policy: TransportCodingPolicy,
+    /// Congestion control for symbol pacing, run once per path.
+    pub congestion: CongestionControlConfig,
 }
 
 impl Default for TransportConfig {
@@ -322,6 +335,7 @@ impl Default for TransportConfig {
             path_strategy: PathSelectionStrategy::RoundRobin,
             experiment_gate: ExperimentalTransportGate::Disabled,
             coding_policy: TransportCodingPolicy::Disabled,
+            congestion: CongestionControlConfig::default(),
         }
     }
 }
@@ -350,6 +364,13 @@ impl TransportConfig {
             ..AggregatorConfig::default()
         }
     }
+
+    /// Creates the congestion-controlled send queue of one path. Each call
+    /// gets its own controller, so paths never share congestion state.
+    #[must_use]
+    pub fn path_pacer<T>(&self, path: PathId) -> SymbolPacer<T> {
+        self.congestion.pacer(path)
+    }
 }
 
 /// Backoff configuration for transport retries.
@@ -417,6 +438,11 @@ pub struct ResourceConfig {
     pub max_decoding_ops: usize,
     /// Symbol pool size (buffer count).
     pub symbol_pool_size: usize,
+    /// How the runtime scheduler shares ready dispatches between regions.
+    pub region_fairness: RegionFairness,
+    /// Scheduling weight of regions that have not been given one through
+    /// [`RuntimeHandle::set_region_weight`](crate::runtime::RuntimeHandle::set_region_weight).
+    pub default_region_weight: u32,
 }
 
 impl Default for ResourceConfig {
@@ -426,6 +452,8 @@ impl Default for ResourceConfig {
             max_encoding_ops: 8,
             max_decoding_ops: 8,
             symbol_pool_size: 1024,
+            region_fairness: RegionFairness::TaskFair,
+            default_region_weight: DEFAULT_REGION_WEIGHT,
         }
     }
 }
@@ -701,6 +729,10 @@ pub enum ConfigError {
     /// cannot be honored by the runtime (e.g. `reject_unauthenticated=true`
     /// with `auth_mode=disabled`).
     InconsistentSecurity(String),
+    /// Congestion control settings are inconsistent.
+    InvalidCongestionControl(&'static str),
+    /// Region scheduling weight must be > 0.
+    InvalidRegionWeight,
 }
 
 impl std::fmt::Display for ConfigError {
@@ -727,6 +759,10 @@ impl std::fmt::Display for ConfigError {
             Self::InconsistentSecurity(msg) => {
                 write!(f, "inconsistent security config: {msg}")
             }
+            Self::InvalidCongestionControl(reason) => {
+                write!(f, "invalid congestion control config: {reason}")
+            }
+            Self::InvalidRegionWeight => write!(f, "default_region_weight must be > 0"),
         }
     }
 }
@@ -819,6 +855,9 @@ fn apply_env_override(
         "RAPTORQ_TRANSPORT_PATH_STRATEGY" => {
             config.transport.path_strategy = parse_path_strategy(value, key)?;
         }
+        "RAPTORQ_TRANSPORT_CONGESTION_CONTROL" => {
+            config.transport.congestion.algorithm = parse_congestion_algorithm(value, key)?;
+        }
         "RAPTORQ_RESOURCES_MAX_SYMBOL_BUFFER_MEMORY" => {
             config.resources.max_symbol_buffer_memory = parse_usize(value, key)?;
         }
@@ -831,6 +870,12 @@ fn apply_env_override(
         "RAPTORQ_RESOURCES_SYMBOL_POOL_SIZE" => {
             config.resources.symbol_pool_size = parse_usize(value, key)?;
         }
+        "RAPTORQ_RESOURCES_REGION_FAIRNESS" => {
+            config.resources.region_fairness = parse_region_fairness(value, key)?;
+        }
+        "RAPTORQ_RESOURCES_DEFAULT_REGION_WEIGHT" => {
+            config.resources.default_region_weight = parse_u32(value, key)?;
+        }
         "RAPTORQ_TIMEOUTS_DEFAULT_TIMEOUT_MS" => {
             config.timeouts.default_timeout = parse_duration_ms(value, key)?;
         }
@@ -977,6 +1022,9 @@ fn apply_transport_kv(
         "path_strategy" => transport.path_strategy = parse_path_strategy(value, key)?,
         "experiment_gate" => transport.experiment_gate = parse_experiment_gate(value, key)?,
         "coding_policy" => transport.coding_policy = parse_transport_coding_policy(value, key)?,
+        "congestion_control" => {
+            transport.congestion.algorithm = parse_congestion_algorithm(value, key)?;
+        }
         _ => return Err(ConfigError::Parse(format!("unknown key: transport.{key}"))),
     }
     Ok(())
@@ -994,6 +1042,8 @@ fn apply_resource_kv(
         "max_encoding_ops" => resources.max_encoding_ops = parse_usize(value, key)?,
         "max_decoding_ops" => resources.max_decoding_ops = parse_usize(value, key)?,
         "symbol_pool_size" => resources.symbol_pool_size = parse_usize(value, key)?,
+        "region_fairness" => resources.region_fairness = parse_region_fairness(value, key)?,
+        "default_region_weight" => resources.default_region_weight = parse_u32(value, key)?,
         _ => return Err(ConfigError::Parse(format!("unknown key: resources.{key}"))),
     }
     Ok(())
@@ -1084,6 +1134,12 @@ fn parse_usize(value: &str, key: &str) -> Result<usize, ConfigError> {
         .map_err(|_| ConfigError::Parse(format!("invalid usize for {key}: {value}")))
 }
 
+fn parse_u32(value: &str, key: &str) -> Result<u32, ConfigError> {
+    value
+        .parse::<u32>()
+        .map_err(|_| ConfigError::Parse(format!("invalid u32 for {key}: {value}")))
+}
+
 fn parse_u16(value: &str, key: &str) -> Result<u16, ConfigError> {
     value
         .parse::<u16>()
@@ -1173,6 +1229,26 @@ fn parse_transport_coding_policy(
     }
 }
 
+fn parse_congestion_algorithm(value: &str, key: &str) -> Result<CongestionAlgorithm, ConfigError> {
+    match value.to_lowercase().as_str() {
+        "aimd" => Ok(CongestionAlgorithm::Aimd),
+        "bbr_like" => Ok(CongestionAlgorithm::BbrLike),
+        _ => Err(ConfigError::Parse(format!(
+            "invalid congestion control for {key}: {value}"
+        ))),
+    }
+}
+
+fn parse_region_fairness(value: &str, key: &str) -> Result<RegionFairness, ConfigError> {
+    match value.to_lowercase().as_str() {
+        "task_fair" => Ok(RegionFairness::TaskFair),
+        "region_fair" => Ok(RegionFairness::RegionFair),
+        _ => Err(ConfigError::Parse(format!(
+            "invalid region fairness for {key}: {value}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 #[allow(unsafe_code)]
 mod tests {
@@ -1840,6 +1916,44 @@ reject_unauthenticated = false
         assert_eq!(transport.coding_policy, TransportCodingPolicy::RlncPreview);
     }
 
+    #[test]
+    fn parse_and_validate_transport_congestion_control() {
+        let mut transport = TransportConfig::default();
+        assert_eq!(transport.congestion.algorithm, CongestionAlgorithm::Aimd);
+        apply_transport_kv(&mut transport, "congestion_control", "bbr_like").unwrap();
+        assert_eq!(transport.congestion.algorithm, CongestionAlgorithm::BbrLike);
+        assert!(apply_transport_kv(&mut transport, "congestion_control", "cubic").is_err());
+
+        let pacer = transport.path_pacer::<u32>(PathId(2));
+        assert_eq!(pacer.path(), PathId(2));
+        assert_eq!(pacer.controller().name(), "bbr_like");
+
+        let mut config = RaptorQConfig::default();
+        config.transport.congestion.aimd_decrease_permille = 0;
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidCongestionControl(_)));
+        assert!(err.to_string().contains("aimd_decrease_permille"));
+    }
+
+    #[test]
+    fn parse_and_validate_region_fairness() {
+        let mut resources = ResourceConfig::default();
+        assert_eq!(resources.region_fairness, RegionFairness::TaskFair);
+        assert_eq!(resources.default_region_weight, DEFAULT_REGION_WEIGHT);
+        apply_resource_kv(&mut resources, "region_fairness", "region_fair").unwrap();
+        apply_resource_kv(&mut resources, "default_region_weight", "2048").unwrap();
+        assert_eq!(resources.region_fairness, RegionFairness::RegionFair);
+        assert_eq!(resources.default_region_weight, 2048);
+        assert!(apply_resource_kv(&mut resources, "region_fairness", "lottery").is_err());
+
+        let mut config = RaptorQConfig::default();
+        config.resources.default_region_weight = 0;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidRegionWeight)
+        ));
+    }
+
     #[test]
     fn resource_config_debug_clone_default() {
         let rc = ResourceConfig::default();
@@ -0,0 +1,707 @@
+//! Runtime capability attenuation for derived contexts.
+//!
+//! The type-level [`CapSet`](super::cap::CapSet) row covers the coarse
+//! effects (spawn, time, entropy, I/O, remote) and is checked at compile
+//! time. Attenuation covers the finer-grained authority a caller may want to
+//! withhold from code it does not fully trust — typically a plugin-provided
+//! task that should get "time and this one channel, but no net, no fs, no
+//! spawn elsewhere":
+//!
+//! ```ignore
+//! let plugin_cx = cx.restrict_capabilities(CapabilitySet::none(), "plugin:thumbnailer")?;
+//! plugin_cx.spawn(|child| async move {
+//!     // child.spawn_blocking(..) -> Err(SpawnError::CapabilityDenied(..))
+//!     // TcpStream::connect(..)   -> Err(io::ErrorKind::PermissionDenied)
+//!     run_plugin(child).await
+//! })?;
+//! ```
+//!
+//! # Semantics
+//!
+//! - [`Cx::restrict_capabilities`] returns a *derived* context that shares
+//!   the task identity, budget, and cancellation of its source but carries
+//!   its own [`CapabilityRestriction`]. The source context is unaffected.
+//! - Restrictions only narrow. Requesting a capability the source context
+//!   no longer holds fails with a widening [`CapabilityDenied`]; it is never
+//!   silently granted.
+//! - Tasks spawned through a restricted context inherit the restriction, and
+//!   their task record carries it, so the chain of restriction sites is
+//!   visible to audits through [`TaskRecord::capability_restriction`].
+//! - A denied accessor returns a typed [`CapabilityDenied`] naming the
+//!   capability and the restriction site that removed it. It never panics.
+//!   Every denial is written to the context's trace buffer and log collector
+//!   and emitted as a `warn!` event.
+//!
+//! Explicit accessors (`Cx::spawn_blocking*`, cross-region `Cx::spawn_*_in`,
+//! [`spawn_remote`](crate::remote::spawn_remote)) consult the context they are
+//! called on. Entry points that do not take a `Cx` (TCP/UDP/Unix sockets, file
+//! opens, [`Command::spawn`](crate::process::Command::spawn)) consult the
+//! ambient context of the running task.
+//!
+//! An unrestricted context stores `None`, so checks on the common path are a
+//! single branch and no allocation happens until a restriction is applied.
+//!
+//! [`TaskRecord::capability_restriction`]: crate::record::TaskRecord::capability_restriction
+
+use super::cx::Cx;
+use crate::tracing_compat::warn;
+use crate::types::TaskId;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+/// A runtime capability that can be withheld from a derived [`Cx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Opening sockets: TCP/UDP/Unix connect, bind, and listen.
+    Net,
+    /// Opening files through [`crate::fs::File`] and [`crate::fs::OpenOptions`].
+    Fs,
+    /// Spawning child processes through [`crate::process`].
+    Process,
+    /// Spawning work on remote nodes.
+    RemoteSpawn,
+    /// Dispatching closures to the blocking pool.
+    BlockingPool,
+    /// Spawning into a region other than the context's own.
+    CrossRegionSpawn,
+}
+
+impl Capability {
+    /// Every attenuable capability, in bit order.
+    pub const ALL: [Self; 6] = [
+        Self::Net,
+        Self::Fs,
+        Self::Process,
+        Self::RemoteSpawn,
+        Self::BlockingPool,
+        Self::CrossRegionSpawn,
+    ];
+
+    /// Stable lowercase name used in errors and trace events.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Net => "net",
+            Self::Fs => "fs",
+            Self::Process => "process",
+            Self::RemoteSpawn => "remote_spawn",
+            Self::BlockingPool => "blocking_pool",
+            Self::CrossRegionSpawn => "cross_region_spawn",
+        }
+    }
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A set of attenuable [`Capability`] values.
+///
+/// Built with [`CapabilitySet::none`] plus [`with`](Self::with), or
+/// [`CapabilitySet::all`] minus [`without`](Self::without).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CapabilitySet(u8);
+
+impl CapabilitySet {
+    /// Every attenuable capability.
+    #[must_use]
+    pub const fn all() -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < Capability::ALL.len() {
+            bits |= Capability::ALL[i].bit();
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    /// No attenuable capability.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    /// Returns this set with `capability` added.
+    #[must_use]
+    pub const fn with(self, capability: Capability) -> Self {
+        Self(self.0 | capability.bit())
+    }
+
+    /// Returns this set with `capability` removed.
+    #[must_use]
+    pub const fn without(self, capability: Capability) -> Self {
+        Self(self.0 & !capability.bit())
+    }
+
+    /// Returns true if `capability` is in the set.
+    #[inline]
+    #[must_use]
+    pub const fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    /// Capabilities present in both sets.
+    #[must_use]
+    pub const fn intersect(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Returns true if every capability in `self` is also in `other`.
+    #[must_use]
+    pub const fn is_subset_of(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Returns true if the set is empty.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates the capabilities in the set, in bit order.
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(move |capability| self.contains(*capability))
+    }
+}
+
+impl FromIterator<Capability> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::none(), Self::with)
+    }
+}
+
+impl fmt::Debug for CapabilitySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Display for CapabilitySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, capability) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(capability.as_str())?;
+        }
+        f.write_str("}")
+    }
+}
+
+/// One link in a chain of capability restrictions.
+///
+/// Each link records the set its site asked for and the effective set after
+/// intersecting with every enclosing restriction, so the effective set can
+/// only shrink along the chain.
+#[derive(Debug)]
+pub struct CapabilityRestriction {
+    site: Arc<str>,
+    requested: CapabilitySet,
+    allowed: CapabilitySet,
+    parent: Option<Arc<Self>>,
+}
+
+impl CapabilityRestriction {
+    fn new(site: Arc<str>, requested: CapabilitySet, parent: Option<Arc<Self>>) -> Self {
+        let allowed = parent
+            .as_ref()
+            .map_or(requested, |parent| parent.allowed.intersect(requested));
+        Self {
+            site,
+            requested,
+            allowed,
+            parent,
+        }
+    }
+
+    /// Label of the site that applied this restriction.
+    #[must_use]
+    pub fn site(&self) -> &str {
+        &self.site
+    }
+
+    /// The set requested at this site, before composition.
+    #[must_use]
+    pub const fn requested(&self) -> CapabilitySet {
+        self.requested
+    }
+
+    /// The effective set: this request intersected with every enclosing one.
+    #[must_use]
+    pub const fn allowed(&self) -> CapabilitySet {
+        self.allowed
+    }
+
+    /// The enclosing restriction, if this one was applied to an already
+    /// restricted context.
+    #[must_use]
+    pub fn parent(&self) -> Option<&Self> {
+        self.parent.as_deref()
+    }
+
+    /// Number of restrictions in the chain, including this one.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.chain().count()
+    }
+
+    /// Iterates the chain from this (innermost) restriction outwards.
+    pub fn chain(&self) -> impl Iterator<Item = &Self> {
+        std::iter::successors(Some(self), |link| link.parent())
+    }
+
+    /// The outermost site whose request excluded `capability`, or `None` if
+    /// the capability is allowed.
+    #[must_use]
+    pub fn denied_by(&self, capability: Capability) -> Option<&str> {
+        self.removing_link(capability).map(Self::site)
+    }
+
+    fn removing_link(&self, capability: Capability) -> Option<&Self> {
+        self.chain()
+            .filter(|link| !link.requested.contains(capability))
+            .last()
+    }
+}
+
+/// A capability accessor was refused by a [`CapabilityRestriction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityDenied {
+    capability: Capability,
+    site: Arc<str>,
+    task: TaskId,
+    widening: bool,
+}
+
+impl CapabilityDenied {
+    /// A refusal of a capability the running workspace member's manifest
+    /// does not declare, reported against `site`.
+    pub(super) const fn undeclared(capability: Capability, site: Arc<str>, task: TaskId) -> Self {
+        Self {
+            capability,
+            site,
+            task,
+            widening: false,
+        }
+    }
+
+    /// The capability that was refused.
+    #[must_use]
+    pub const fn capability(&self) -> Capability {
+        self.capability
+    }
+
+    /// Label of the restriction site that removed the capability.
+    #[must_use]
+    pub fn site(&self) -> &str {
+        &self.site
+    }
+
+    /// The task whose context was refused.
+    #[must_use]
+    pub const fn task(&self) -> TaskId {
+        self.task
+    }
+
+    /// Returns true if the refusal was an attempt to widen a restriction
+    /// rather than to use a withheld capability.
+    #[must_use]
+    pub const fn is_widening(&self) -> bool {
+        self.widening
+    }
+}
+
+impl fmt::Display for CapabilityDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.widening {
+            write!(
+                f,
+                "cannot widen capability `{}` removed by restriction `{}` (task {:?})",
+                self.capability, self.site, self.task
+            )
+        } else {
+            write!(
+                f,
+                "capability `{}` denied by restriction `{}` (task {:?})",
+                self.capability, self.site, self.task
+            )
+        }
+    }
+}
+
+impl std::error::Error for CapabilityDenied {}
+
+impl From<CapabilityDenied> for io::Error {
+    fn from(denied: CapabilityDenied) -> Self {
+        Self::new(io::ErrorKind::PermissionDenied, denied)
+    }
+}
+
+impl<Caps> Cx<Caps> {
+    /// Derives a context whose attenuable capabilities are limited to
+    /// `allowed`.
+    ///
+    /// The derived context shares this context's task identity, budget, and
+    /// cancellation; only its capability view differs. Pass it to
+    /// [`Cx::spawn`] (or hand it to untrusted code directly) and every task
+    /// spawned through it inherits the restriction. `site` labels the
+    /// restriction in denials, trace events, and task records.
+    ///
+    /// # Errors
+    ///
+    /// Returns a widening [`CapabilityDenied`] if `allowed` names a
+    /// capability this context has already lost.
+    pub fn restrict_capabilities(
+        &self,
+        allowed: CapabilitySet,
+        site: impl Into<Arc<str>>,
+    ) -> Result<Self, CapabilityDenied> {
+        let site = site.into();
+        let parent = self.capability_restriction.clone();
+        if let Some(parent) = &parent
+            && let Some(capability) = allowed.iter().find(|c| !parent.allowed.contains(*c))
+        {
+            return Err(self.deny(parent, capability, true));
+        }
+        let restriction = Arc::new(CapabilityRestriction::new(
+            Arc::clone(&site),
+            allowed,
+            parent,
+        ));
+        self.trace(&format!(
+            "capability restriction `{site}` applied: allowed={} depth={}",
+            restriction.allowed,
+            restriction.depth()
+        ));
+        let mut derived = self.clone();
+        derived.capability_restriction = Some(restriction);
+        Ok(derived)
+    }
+
+    /// The restriction carried by this context, if any.
+    #[inline]
+    #[must_use]
+    pub fn capability_restriction(&self) -> Option<&CapabilityRestriction> {
+        self.capability_restriction.as_deref()
+    }
+
+    /// The attenuable capabilities this context may use.
+    #[must_use]
+    pub fn allowed_capabilities(&self) -> CapabilitySet {
+        self.capability_restriction
+            .as_ref()
+            .map_or(CapabilitySet::all(), |restriction| restriction.allowed)
+    }
+
+    /// Checks that this context may use `capability`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CapabilityDenied`] if a restriction on this context withheld
+    /// `capability`, or if an enforcing [`CapabilityAudit`] is installed and
+    /// its manifest does not declare `capability`. The denial is recorded to
+    /// observability before returning.
+    ///
+    /// [`CapabilityAudit`]: super::CapabilityAudit
+    #[inline]
+    pub fn check_capability(&self, capability: Capability) -> Result<(), CapabilityDenied> {
+        if let Some(restriction) = &self.capability_restriction {
+            self.check_restricted(restriction, capability)?;
+        }
+        self.audit_capability(capability)
+    }
+
+    /// Like [`Cx::check_capability`], but also honors the restriction the
+    /// runtime recorded for this task when it was spawned through a
+    /// restricted context. Used by entry points that only see the ambient
+    /// task context.
+    pub(crate) fn check_task_capability(
+        &self,
+        capability: Capability,
+    ) -> Result<(), CapabilityDenied> {
+        if let Some(restriction) = self.task_capability_restriction() {
+            self.check_restricted(&restriction, capability)?;
+        }
+        self.audit_capability(capability)
+    }
+
+    /// Reports a permitted use of `capability` to the runtime's capability
+    /// audit, if one is installed.
+    #[inline]
+    fn audit_capability(&self, capability: Capability) -> Result<(), CapabilityDenied> {
+        self.capability_audit()
+            .map_or(Ok(()), |audit| audit.record(self, capability))
+    }
+
+    /// This context's restriction, falling back to the one the runtime
+    /// recorded for its task.
+    pub(crate) fn task_capability_restriction(&self) -> Option<Arc<CapabilityRestriction>> {
+        self.capability_restriction
+            .clone()
+            .or_else(|| self.inner.read().capability_restriction.clone())
+    }
+
+    fn check_restricted(
+        &self,
+        restriction: &CapabilityRestriction,
+        capability: Capability,
+    ) -> Result<(), CapabilityDenied> {
+        if restriction.allowed.contains(capability) {
+            Ok(())
+        } else {
+            Err(self.deny(restriction, capability, false))
+        }
+    }
+
+    #[cold]
+    fn deny(
+        &self,
+        restriction: &CapabilityRestriction,
+        capability: Capability,
+        widening: bool,
+    ) -> CapabilityDenied {
+        let denied = CapabilityDenied {
+            capability,
+            site: Arc::clone(
+                &restriction
+                    .removing_link(capability)
+                    .unwrap_or(restriction)
+                    .site,
+            ),
+            task: self.task_id(),
+            widening,
+        };
+        warn!(
+            task_id = ?denied.task,
+            capability = capability.as_str(),
+            site = %denied.site,
+            widening,
+            "capability denied by restriction"
+        );
+        self.trace(&denied.to_string());
+        denied
+    }
+
+    /// Records this context's restriction on the task it was inherited by,
+    /// so the task record and ambient lookups observe it.
+    pub(crate) fn record_capability_restriction(&self) {
+        if let Some(restriction) = &self.capability_restriction {
+            self.inner.write().capability_restriction = Some(Arc::clone(restriction));
+        }
+    }
+}
+
+/// Checks `capability` against the ambient task context, if one is installed.
+///
+/// Code running outside any task is not subject to attenuation.
+pub(crate) fn check_ambient_capability(capability: Capability) -> Result<(), CapabilityDenied> {
+    Cx::with_current(|cx| cx.check_task_capability(capability)).unwrap_or(Ok(()))
+}
+
+/// [`check_ambient_capability`] for entry points that return `io::Result`;
+/// a denial surfaces as [`io::ErrorKind::PermissionDenied`] wrapping the
+/// [`CapabilityDenied`].
+pub(crate) fn check_ambient_io(capability: Capability) -> io::Result<()> {
+    check_ambient_capability(capability).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::TraceBufferHandle;
+    use crate::trace::event::{TraceData, TraceEventKind};
+
+    fn trace_messages(trace: &TraceBufferHandle) -> Vec<String> {
+        trace
+            .snapshot()
+            .into_iter()
+            .filter(|event| event.kind == TraceEventKind::UserTrace)
+            .filter_map(|event| match event.data {
+                TraceData::Message(message) => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn each_capability_class_is_denied_or_allowed_as_configured() {
+        let cx = Cx::for_testing();
+        for capability in Capability::ALL {
+            let denied = cx
+                .restrict_capabilities(CapabilitySet::all().without(capability), "deny-one")
+                .expect("narrowing succeeds");
+            let err = denied
+                .check_capability(capability)
+                .expect_err("withheld capability is denied");
+            assert_eq!(err.capability(), capability);
+            assert_eq!(err.site(), "deny-one");
+            assert_eq!(err.task(), cx.task_id());
+            assert!(!err.is_widening());
+            for other in Capability::ALL.into_iter().filter(|c| *c != capability) {
+                assert!(
+                    denied.check_capability(other).is_ok(),
+                    "{other} stays allowed"
+                );
+            }
+
+            let allowed = cx
+                .restrict_capabilities(CapabilitySet::none().with(capability), "allow-one")
+                .expect("narrowing succeeds");
+            assert!(allowed.check_capability(capability).is_ok());
+            assert_eq!(
+                allowed.allowed_capabilities(),
+                CapabilitySet::none().with(capability)
+            );
+        }
+    }
+
+    #[test]
+    fn restriction_does_not_affect_source_context() {
+        let cx = Cx::for_testing();
+        let restricted = cx
+            .restrict_capabilities(CapabilitySet::none(), "plugin")
+            .expect("narrowing succeeds");
+        assert!(restricted.check_capability(Capability::Net).is_err());
+        assert!(cx.check_capability(Capability::Net).is_ok());
+        assert!(cx.capability_restriction().is_none());
+        assert_eq!(restricted.task_id(), cx.task_id());
+    }
+
+    #[test]
+    fn widening_is_rejected() {
+        let cx = Cx::for_testing();
+        let no_net = cx
+            .restrict_capabilities(CapabilitySet::all().without(Capability::Net), "outer")
+            .expect("narrowing succeeds");
+
+        let err = no_net
+            .restrict_capabilities(CapabilitySet::none().with(Capability::Net), "inner")
+            .expect_err("re-granting net is widening");
+        assert!(err.is_widening());
+        assert_eq!(err.capability(), Capability::Net);
+        assert_eq!(err.site(), "outer");
+
+        let narrower = no_net
+            .restrict_capabilities(CapabilitySet::none().with(Capability::Fs), "inner")
+            .expect("narrowing further succeeds");
+        assert_eq!(
+            narrower.allowed_capabilities(),
+            CapabilitySet::none().with(Capability::Fs)
+        );
+    }
+
+    #[test]
+    fn composed_restrictions_report_the_outermost_removing_site() {
+        let cx = Cx::for_testing();
+        let outer = cx
+            .restrict_capabilities(CapabilitySet::all().without(Capability::Process), "outer")
+            .expect("outer");
+        let inner = outer
+            .restrict_capabilities(
+                CapabilitySet::all()
+                    .without(Capability::Process)
+                    .without(Capability::Fs),
+                "inner",
+            )
+            .expect("inner");
+        let restriction = inner.capability_restriction().expect("restricted");
+        assert_eq!(restriction.depth(), 2);
+        assert_eq!(
+            restriction
+                .chain()
+                .map(CapabilityRestriction::site)
+                .collect::<Vec<_>>(),
+            ["inner", "outer"]
+        );
+        assert_eq!(restriction.denied_by(Capability::Process), Some("outer"));
+        assert_eq!(restriction.denied_by(Capability::Fs), Some("inner"));
+        assert_eq!(restriction.denied_by(Capability::Net), None);
+        assert_eq!(
+            inner
+                .check_capability(Capability::Process)
+                .expect_err("denied")
+                .site(),
+            "outer"
+        );
+    }
+
+    #[test]
+    fn restriction_and_denials_are_traced() {
+        let cx = Cx::for_testing();
+        let trace = TraceBufferHandle::new(16);
+        cx.set_trace_buffer(trace.clone());
+
+        let restricted = cx
+            .restrict_capabilities(CapabilitySet::none(), "plugin:audit")
+            .expect("narrowing succeeds");
+        let _ = restricted.check_capability(Capability::BlockingPool);
+
+        let messages = trace_messages(&trace);
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].contains("plugin:audit"));
+        assert!(messages[0].contains("allowed={}"));
+        assert!(messages[1].contains("blocking_pool"));
+        assert!(messages[1].contains("plugin:audit"));
+    }
+
+    #[test]
+    fn ambient_check_honors_installed_restricted_context() {
+        let cx = Cx::for_testing();
+        assert!(check_ambient_capability(Capability::Fs).is_ok());
+        let restricted = cx
+            .restrict_capabilities(CapabilitySet::none(), "ambient")
+            .expect("narrowing succeeds");
+        {
+            let _guard = Cx::set_current(Some(restricted));
+            let err = check_ambient_capability(Capability::Fs).expect_err("denied");
+            assert_eq!(err.site(), "ambient");
+            let io_err = io::Error::from(err);
+            assert_eq!(io_err.kind(), io::ErrorKind::PermissionDenied);
+        }
+        let _guard = Cx::set_current(Some(cx));
+        assert!(check_ambient_capability(Capability::Fs).is_ok());
+    }
+
+    #[test]
+    fn unrestricted_context_carries_no_restriction_state() {
+        // Benchmark guard: the unrestricted path is a pointer-sized `None`
+        // and a single branch per check; nothing is allocated or locked.
+        assert_eq!(
+            std::mem::size_of::<Option<Arc<CapabilityRestriction>>>(),
+            std::mem::size_of::<usize>()
+        );
+        let cx = Cx::for_testing();
+        assert!(cx.capability_restriction().is_none());
+        assert_eq!(cx.allowed_capabilities(), CapabilitySet::all());
+        for _ in 0..10_000 {
+            assert!(cx.check_capability(Capability::Net).is_ok());
+        }
+        assert!(cx.clone().capability_restriction().is_none());
+    }
+
+    #[test]
+    fn capability_set_operations() {
+        let set: CapabilitySet = [Capability::Net, Capability::Fs].into_iter().collect();
+        assert!(set.contains(Capability::Net));
+        assert!(!set.contains(Capability::Process));
+        assert!(set.is_subset_of(CapabilitySet::all()));
+        assert!(!CapabilitySet::all().is_subset_of(set));
+        assert!(CapabilitySet::none().is_empty());
+        assert_eq!(
+            set.intersect(CapabilitySet::none().with(Capability::Fs)),
+            CapabilitySet::none().with(Capability::Fs)
+        );
+        assert_eq!(set.to_string(), "{net,fs}");
+        assert_eq!(CapabilitySet::all().iter().count(), Capability::ALL.len());
+    }
+}
@@ -0,0 +1,434 @@
+//! Cancellation points for CPU-bound loops.
+//!
+//! A task that computes for hundreds of milliseconds between awaits leaves
+//! cancellation requests unobserved for that long, and its region cannot
+//! finish draining until it stops. [`Cx::checkpoint`] can be called inside
+//! the loop, but it records progress and re-checks the budget under the
+//! context lock on every call, which is too much for an inner loop. This
+//! module adds lighter forms that report cancellation as
+//! [`ControlFlow::Break`] carrying the [`CancelReason`]:
+//!
+//! - [`Cx::checkpoint_every`] returns a [`CancelPoints`] handle for one
+//!   loop. [`CancelPoints::check`] counts down and only every `n`th call
+//!   reads the task's cancel flag, with a single relaxed atomic load: no
+//!   lock, no allocation, nothing shared is written.
+//! - [`Cx::cancel_point`] is the one-off form, for code that has no loop
+//!   state to keep. It is wait-free too: the context caches the flag, so the
+//!   happy path is the same single relaxed load.
+//! - [`Cx::yield_if_cancelled`] is the async form: it yields to the
+//!   scheduler once and reports cancellation before and after.
+//!
+//! The flag is the one every cancellation source raises: cancel requests on
+//! the task or its regions, deadline expiry, and poll-quota exhaustion. The
+//! cooperative budget therefore surfaces through the same points, and a
+//! loop that yields through [`Cx::yield_if_cancelled`] spends its poll
+//! quota like any other await.
+//!
+//! Only a raised flag takes the slow path, which locks the context, honours
+//! [`Cx::masked`] sections (a masked point reports `Continue`), and marks
+//! the cancellation acknowledged.
+//!
+//! # Drain Contract
+//!
+//! Cancelling a region gives its tasks a bounded cleanup budget. A loop
+//! that calls [`CancelPoints::check`] once per iteration, with iterations
+//! costing at most `c`, observes a request at most `n * c` after it reaches
+//! the task, where `n` is the interval passed to [`Cx::checkpoint_every`].
+//! Pick `n` so that `n * c` fits the cleanup budget and the loop satisfies
+//! bounded cleanup; [`CancelPoints::observation_bound`] computes the bound.
+//! Without a point inside the loop the bound is the full time between
+//! awaits.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::ops::ControlFlow;
+//!
+//! let mut points = cx.checkpoint_every(256);
+//! for pixel in image.pixels_mut() {
+//!     if let ControlFlow::Break(reason) = points.check() {
+//!         return Err(Error::cancelled(&reason));
+//!     }
+//!     *pixel = filter(*pixel);
+//! }
+//! ```
+
+use super::cap;
+use super::cx::Cx;
+use crate::types::{CancelKind, CancelReason};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Amortized cancellation checks for one loop, from
+/// [`Cx::checkpoint_every`].
+#[derive(Debug)]
+pub struct CancelPoints<Caps = cap::All> {
+    cx: Cx<Caps>,
+    flag: Arc<AtomicBool>,
+    interval: u32,
+    countdown: u32,
+}
+
+impl<Caps> CancelPoints<Caps> {
+    /// Counts one iteration; every `interval`th call reads the cancel flag.
+    /// Returns `Break` with the reason once cancellation is observed.
+    #[inline]
+    pub fn check(&mut self) -> ControlFlow<CancelReason> {
+        self.countdown -= 1;
+        if self.countdown > 0 {
+            return ControlFlow::Continue(());
+        }
+        self.countdown = self.interval;
+        self.check_now()
+    }
+
+    /// Reads the cancel flag now, regardless of the countdown.
+    #[inline]
+    pub fn check_now(&self) -> ControlFlow<CancelReason> {
+        if self.flag.load(Ordering::Relaxed) {
+            self.cx.observe_cancel()
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Returns the number of calls to [`Self::check`] per flag read.
+    #[must_use]
+    pub const fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Returns how long after a request reaches the task the loop observes
+    /// it, if each iteration costs at most `per_iteration`.
+    #[must_use]
+    pub const fn observation_bound(&self, per_iteration: Duration) -> Duration {
+        per_iteration.saturating_mul(self.interval)
+    }
+
+    /// Returns the context the points check.
+    #[must_use]
+    pub const fn cx(&self) -> &Cx<Caps> {
+        &self.cx
+    }
+}
+
+impl<Caps> Cx<Caps> {
+    /// Returns `Break` with the cancellation reason if cancellation has been
+    /// requested and is not masked, `Continue` otherwise.
+    ///
+    /// Unlike [`Cx::checkpoint`] this records no progress and does not
+    /// re-check the budget itself; it observes budget exhaustion once the
+    /// runtime has raised it. Unless the flag is raised this is a single
+    /// relaxed load: no lock, no write.
+    #[inline]
+    #[must_use]
+    pub fn cancel_point(&self) -> ControlFlow<CancelReason> {
+        if self.fast_cancel.load(Ordering::Relaxed) {
+            self.observe_cancel()
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Returns cancellation points that read the cancel flag on every
+    /// `interval`th [`CancelPoints::check`] (at least 1). See the
+    /// [module docs](super::cancel_point) for choosing the interval.
+    #[must_use]
+    pub fn checkpoint_every(&self, interval: u32) -> CancelPoints<Caps> {
+        let interval = interval.max(1);
+        CancelPoints {
+            cx: self.clone(),
+            flag: Arc::clone(&self.fast_cancel),
+            interval,
+            countdown: interval,
+        }
+    }
+
+    /// Yields to the scheduler once, reporting cancellation observed before
+    /// or after the yield.
+    ///
+    /// The yield costs one poll of the task's budget, so poll-quota
+    /// exhaustion is reported here like any other cancellation.
+    pub async fn yield_if_cancelled(&self) -> ControlFlow<CancelReason> {
+        self.cancel_point()?;
+        crate::runtime::yield_now().await;
+        self.cancel_point()
+    }
+
+    /// Slow path behind a raised flag.
+    fn observe_cancel(&self) -> ControlFlow<CancelReason> {
+        let mut inner = self.inner.write();
+        if !inner.cancel_requested || inner.mask_depth > 0 {
+            return ControlFlow::Continue(());
+        }
+        inner.cancel_acknowledged = true;
+        let reason = inner.cancel_reason.clone().unwrap_or_else(|| {
+            CancelReason::new(CancelKind::User)
+                .with_region(inner.region)
+                .with_task(inner.task)
+        });
+        drop(inner);
+        ControlFlow::Break(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::types::Budget;
+    use parking_lot::Mutex;
+    use std::sync::atomic::AtomicU64;
+    use std::time::Instant;
+
+    /// Units of work a loop runs between awaits.
+    const CHUNK: u64 = 50_000;
+    /// Units of work the drain phase allows after a request.
+    const DRAIN_BOUND: u64 = 1_000;
+    /// Interval of the checkpointing loop.
+    const INTERVAL: u32 = 256;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Runs chunks of work until cancelled, checking before each await and,
+    /// if `checkpointing`, inside the chunk. Returns the units of work done
+    /// after `issued` was raised.
+    async fn crunch(
+        checkpointing: bool,
+        issued: Arc<AtomicBool>,
+        cxs: Arc<Mutex<Vec<Cx>>>,
+    ) -> u64 {
+        let cx = Cx::current().expect("task context");
+        cxs.lock().push(cx.clone());
+        let mut points = cx.checkpoint_every(INTERVAL);
+        let mut overrun = 0;
+        let mut acc = 0_u64;
+        loop {
+            for unit in 0..CHUNK {
+                if issued.load(Ordering::Relaxed) {
+                    overrun += 1;
+                }
+                acc = std::hint::black_box(acc.wrapping_mul(31).wrapping_add(unit));
+                if checkpointing && points.check().is_break() {
+                    return overrun;
+                }
+            }
+            if cx.checkpoint().is_err() {
+                return overrun;
+            }
+            crate::runtime::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn checkpointing_loop_drains_within_budget() {
+        init_test("checkpointing_loop_drains_within_budget");
+        let mut lab = LabRuntime::new(LabConfig::new(0x0C_4EC4));
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let issued = Arc::new(AtomicBool::new(false));
+        let cxs = Arc::new(Mutex::new(Vec::new()));
+
+        let mut handles = Vec::new();
+        for checkpointing in [true, false] {
+            let fut = crunch(checkpointing, Arc::clone(&issued), Arc::clone(&cxs));
+            let (task, handle) = lab
+                .state
+                .create_task(root, Budget::INFINITE, fut)
+                .expect("spawn cruncher");
+            lab.scheduler.lock().schedule(task, 0);
+            handles.push(handle);
+        }
+        let canceller = {
+            let issued = Arc::clone(&issued);
+            let cxs = Arc::clone(&cxs);
+            async move {
+                while cxs.lock().len() < 2 {
+                    crate::runtime::yield_now().await;
+                }
+                for cx in cxs.lock().iter() {
+                    cx.cancel_with(CancelKind::User, Some("image job aborted"));
+                }
+                issued.store(true, Ordering::Relaxed);
+            }
+        };
+        let (task, _canceller) = lab
+            .state
+            .create_task(root, Budget::INFINITE, canceller)
+            .expect("spawn canceller");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_until_quiescent();
+
+        let mut overruns = handles.into_iter().map(|mut handle| {
+            handle
+                .try_join()
+                .expect("join")
+                .expect("cruncher finished")
+        });
+        let checkpointing = overruns.next().expect("checkpointing");
+        let naive = overruns.next().expect("naive");
+        crate::assert_with_log!(
+            checkpointing <= u64::from(INTERVAL) && checkpointing <= DRAIN_BOUND,
+            "checkpointing loop stops within its interval",
+            INTERVAL,
+            checkpointing
+        );
+        crate::assert_with_log!(
+            naive == CHUNK && naive > DRAIN_BOUND,
+            "naive loop runs to its next await",
+            CHUNK,
+            naive
+        );
+        crate::test_complete!("checkpointing_loop_drains_within_budget");
+    }
+
+    #[test]
+    fn happy_path_takes_no_lock() {
+        init_test("happy_path_takes_no_lock");
+        let cx = Cx::for_testing();
+        let mut points = cx.checkpoint_every(1);
+
+        // Holding the context's write lock would deadlock any check that
+        // touched it.
+        let guard = cx.inner.write();
+        let started = Instant::now();
+        let mut continued = 0_u64;
+        for _ in 0..1_000_000 {
+            if points.check().is_continue() && cx.cancel_point().is_continue() {
+                continued += 1;
+            }
+        }
+        let elapsed = started.elapsed();
+        drop(guard);
+        crate::assert_with_log!(continued == 1_000_000, "all continue", 1_000_000, continued);
+        // Generous enough for unoptimized builds on a loaded machine; a lock
+        // or allocation per check would blow through it.
+        crate::assert_with_log!(
+            elapsed < Duration::from_millis(500),
+            "one million checks are cheap",
+            "< 500ms",
+            elapsed
+        );
+        crate::test_complete!("happy_path_takes_no_lock");
+    }
+
+    #[test]
+    fn checkpoint_every_amortizes_flag_reads() {
+        init_test("checkpoint_every_amortizes_flag_reads");
+        let cx = Cx::for_testing();
+        let mut points = cx.checkpoint_every(4);
+        for call in 1..=5 {
+            let flow = points.check();
+            crate::assert_with_log!(flow.is_continue(), "healthy", "Continue", call);
+        }
+
+        cx.cancel_with(CancelKind::User, Some("stop"));
+        let mut observed_at = None;
+        for call in 6..=12 {
+            if let ControlFlow::Break(reason) = points.check() {
+                crate::assert_with_log!(
+                    reason.kind() == CancelKind::User,
+                    "reason",
+                    CancelKind::User,
+                    reason.kind()
+                );
+                observed_at = Some(call);
+                break;
+            }
+        }
+        // Flag reads happen on calls 4, 8, 12, ...
+        crate::assert_with_log!(observed_at == Some(8), "observed on read", 8, observed_at);
+
+        let masked = cx.masked(|| points.check_now().is_continue());
+        crate::assert_with_log!(masked, "masked point continues", true, masked);
+        let once = cx.cancel_point().is_break();
+        crate::assert_with_log!(once, "one-off point", true, once);
+
+        let clamped = cx.checkpoint_every(0).interval();
+        crate::assert_with_log!(clamped == 1, "interval clamped", 1, clamped);
+        let bound = points.observation_bound(Duration::from_micros(10));
+        crate::assert_with_log!(
+            bound == Duration::from_micros(40),
+            "observation bound",
+            "40us",
+            bound
+        );
+        crate::test_complete!("checkpoint_every_amortizes_flag_reads");
+    }
+
+    #[test]
+    fn cancel_points_and_poll_budget_do_not_starve_each_other() {
+        init_test("cancel_points_and_poll_budget_do_not_starve_each_other");
+        let mut lab = LabRuntime::new(LabConfig::new(0xB0D6E7));
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let sibling_steps = Arc::new(AtomicU64::new(0));
+
+        let cruncher = async move {
+            let cx = Cx::current().expect("task context");
+            let mut points = cx.checkpoint_every(64);
+            let mut acc = 0_u64;
+            let mut yields = 0_u32;
+            for unit in 0_u64.. {
+                acc = std::hint::black_box(acc.wrapping_add(unit));
+                if let ControlFlow::Break(reason) = points.check() {
+                    return (reason, yields);
+                }
+                if unit % 1_000 == 999 {
+                    if let ControlFlow::Break(reason) = cx.yield_if_cancelled().await {
+                        return (reason, yields);
+                    }
+                    yields += 1;
+                }
+            }
+            unreachable!("the poll quota ends the loop")
+        };
+        let (task, mut cruncher) = lab
+            .state
+            .create_task(root, Budget::INFINITE.with_poll_quota(6), cruncher)
+            .expect("spawn cruncher");
+        lab.scheduler.lock().schedule(task, 0);
+
+        let sibling = {
+            let sibling_steps = Arc::clone(&sibling_steps);
+            async move {
+                for _ in 0..20 {
+                    sibling_steps.fetch_add(1, Ordering::Relaxed);
+                    crate::runtime::yield_now().await;
+                }
+            }
+        };
+        let (task, _sibling) = lab
+            .state
+            .create_task(root, Budget::INFINITE, sibling)
+            .expect("spawn sibling");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_until_quiescent();
+
+        let (reason, yields) = cruncher
+            .try_join()
+            .expect("join")
+            .expect("cruncher finished");
+        crate::assert_with_log!(
+            reason.kind() == CancelKind::PollQuota,
+            "poll quota surfaces at a cancel point",
+            CancelKind::PollQuota,
+            reason.kind()
+        );
+        crate::assert_with_log!(yields < 6, "yields spend the quota", "< 6", yields);
+        let steps = sibling_steps.load(Ordering::Relaxed);
+        crate::assert_with_log!(steps == 20, "sibling ran to completion", 20, steps);
+        crate::test_complete!("cancel_points_and_poll_budget_do_not_starve_each_other");
+    }
+}
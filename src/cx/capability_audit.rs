@@ -0,0 +1,326 @@
+//! Runtime capability-usage audit against a declared manifest.
+//!
+//! A [`CapabilityAudit`] installed on a runtime (see
+//! [`RuntimeBuilder::capability_audit`](crate::runtime::RuntimeBuilder::capability_audit))
+//! observes every attenuable capability check made by the runtime's tasks:
+//! the same accessors that consult a [`CapabilityRestriction`] also report
+//! to the audit. The first use of each capability in each region is recorded
+//! as a [`CapabilityUse`] and emitted as an audit event; repeated uses in the
+//! same region are not recorded again.
+//!
+//! The collected log ([`CapabilityAudit::usage_log_json`]) is what
+//! `asupersync doctor capability-audit --usage-log` diffs against the
+//! workspace's capability manifests, alongside its static scan.
+//!
+//! In [`CapabilityAuditMode::Enforce`] a use outside the declared set fails
+//! with [`CapabilityDenied`] whose site names the manifest, through the same
+//! error paths a restriction denial takes (`SpawnError::CapabilityDenied`,
+//! `io::ErrorKind::PermissionDenied`). A restriction is checked first, so a
+//! capability withheld by a restriction is reported against that restriction.
+//!
+//! [`CapabilityRestriction`]: super::CapabilityRestriction
+
+use super::attenuation::{Capability, CapabilityDenied, CapabilitySet};
+use super::cx::Cx;
+use crate::tracing_compat::{info, warn};
+use crate::types::{RegionId, TaskId};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Schema version of [`CapabilityAudit::usage_log_json`].
+pub const CAPABILITY_USAGE_LOG_SCHEMA_VERSION: &str = "capability-usage-log-v1";
+
+/// What a [`CapabilityAudit`] does with a use outside the declared set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CapabilityAuditMode {
+    /// Record the use and allow it.
+    #[default]
+    Observe,
+    /// Record the use and refuse it with [`CapabilityDenied`].
+    Enforce,
+}
+
+impl CapabilityAuditMode {
+    /// Stable lowercase name used in usage logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Observe => "observe",
+            Self::Enforce => "enforce",
+        }
+    }
+}
+
+/// The first use of a capability in a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityUse {
+    /// Region the using task belongs to.
+    pub region: RegionId,
+    /// Task that made the first use.
+    pub task: TaskId,
+    /// The capability used.
+    pub capability: Capability,
+    /// Whether the manifest declares the capability.
+    pub declared: bool,
+}
+
+/// Records capability uses against a declared [`CapabilitySet`].
+pub struct CapabilityAudit {
+    member: Arc<str>,
+    site: Arc<str>,
+    declared: CapabilitySet,
+    mode: CapabilityAuditMode,
+    state: Mutex<AuditState>,
+}
+
+#[derive(Default)]
+struct AuditState {
+    seen: HashSet<(RegionId, Capability)>,
+    uses: Vec<CapabilityUse>,
+}
+
+impl CapabilityAudit {
+    /// Creates an audit for workspace member `member`, which declares the
+    /// capabilities in `declared`.
+    #[must_use]
+    pub fn new(
+        member: impl Into<Arc<str>>,
+        declared: CapabilitySet,
+        mode: CapabilityAuditMode,
+    ) -> Self {
+        let member = member.into();
+        Self {
+            site: format!("capability-manifest:{member}").into(),
+            member,
+            declared,
+            mode,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The workspace member whose manifest this audit checks.
+    #[must_use]
+    pub fn member(&self) -> &str {
+        &self.member
+    }
+
+    /// The declared capabilities.
+    #[must_use]
+    pub const fn declared(&self) -> CapabilitySet {
+        self.declared
+    }
+
+    /// What the audit does with undeclared uses.
+    #[must_use]
+    pub const fn mode(&self) -> CapabilityAuditMode {
+        self.mode
+    }
+
+    /// First uses recorded so far, in the order they happened.
+    #[must_use]
+    pub fn uses(&self) -> Vec<CapabilityUse> {
+        self.state.lock().uses.clone()
+    }
+
+    /// Every capability used so far, declared or not.
+    #[must_use]
+    pub fn observed(&self) -> CapabilitySet {
+        self.state
+            .lock()
+            .uses
+            .iter()
+            .map(|used| used.capability)
+            .collect()
+    }
+
+    /// Capabilities used so far that the manifest does not declare.
+    #[must_use]
+    pub fn undeclared(&self) -> CapabilitySet {
+        let observed = self.observed();
+        observed
+            .iter()
+            .filter(|capability| !self.declared.contains(*capability))
+            .collect()
+    }
+
+    /// Renders the recorded uses as a JSON usage log.
+    #[must_use]
+    pub fn usage_log_json(&self) -> String {
+        let uses = self
+            .uses()
+            .into_iter()
+            .map(|used| {
+                serde_json::json!({
+                    "capability": used.capability.as_str(),
+                    "region": used.region.to_string(),
+                    "task": used.task.to_string(),
+                    "declared": used.declared,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "schema_version": CAPABILITY_USAGE_LOG_SCHEMA_VERSION,
+            "member": &*self.member,
+            "mode": self.mode.as_str(),
+            "uses": uses,
+        })
+        .to_string()
+    }
+
+    /// Records a use of `capability` by `cx`, refusing it in enforce mode
+    /// when it is undeclared.
+    pub(super) fn record<Caps>(
+        &self,
+        cx: &Cx<Caps>,
+        capability: Capability,
+    ) -> Result<(), CapabilityDenied> {
+        let region = cx.region_id();
+        let task = cx.task_id();
+        let declared = self.declared.contains(capability);
+        let first = {
+            let mut state = self.state.lock();
+            let first = state.seen.insert((region, capability));
+            if first {
+                state.uses.push(CapabilityUse {
+                    region,
+                    task,
+                    capability,
+                    declared,
+                });
+            }
+            first
+        };
+        if first {
+            if declared {
+                info!(
+                    member = %self.member,
+                    region = %region,
+                    task = %task,
+                    capability = capability.as_str(),
+                    "capability first used in region"
+                );
+            } else {
+                warn!(
+                    member = %self.member,
+                    region = %region,
+                    task = %task,
+                    capability = capability.as_str(),
+                    mode = self.mode.as_str(),
+                    "undeclared capability first used in region"
+                );
+            }
+            cx.trace(&format!(
+                "capability audit: `{capability}` first used in {region} by {task} \
+                 (declared={declared})"
+            ));
+        }
+        if declared || self.mode == CapabilityAuditMode::Observe {
+            return Ok(());
+        }
+        let denied = CapabilityDenied::undeclared(capability, Arc::clone(&self.site), task);
+        cx.trace(&denied.to_string());
+        Err(denied)
+    }
+}
+
+impl fmt::Debug for CapabilityAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapabilityAudit")
+            .field("member", &self.member)
+            .field("declared", &self.declared)
+            .field("mode", &self.mode)
+            .field("uses", &self.state.lock().uses.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cx::attenuation::check_ambient_io;
+    use std::io;
+
+    fn audited(declared: CapabilitySet, mode: CapabilityAuditMode) -> (Cx, Arc<CapabilityAudit>) {
+        let audit = Arc::new(CapabilityAudit::new("svc", declared, mode));
+        let cx = Cx::for_testing().with_capability_audit(Some(Arc::clone(&audit)));
+        (cx, audit)
+    }
+
+    #[test]
+    fn first_use_per_region_is_recorded_once() {
+        let declared = CapabilitySet::none().with(Capability::Fs);
+        let (cx, audit) = audited(declared, CapabilityAuditMode::Observe);
+
+        for _ in 0..3 {
+            assert!(cx.check_capability(Capability::Fs).is_ok());
+            assert!(cx.check_capability(Capability::Net).is_ok());
+        }
+
+        let uses = audit.uses();
+        assert_eq!(uses.len(), 2, "{uses:?}");
+        assert_eq!(uses[0].capability, Capability::Fs);
+        assert!(uses[0].declared);
+        assert_eq!(uses[1].capability, Capability::Net);
+        assert!(!uses[1].declared);
+        assert_eq!(uses[1].region, cx.region_id());
+        assert_eq!(uses[1].task, cx.task_id());
+        assert_eq!(audit.undeclared(), CapabilitySet::none().with(Capability::Net));
+    }
+
+    #[test]
+    fn enforce_mode_denies_undeclared_capabilities() {
+        let declared = CapabilitySet::none().with(Capability::BlockingPool);
+        let (cx, audit) = audited(declared, CapabilityAuditMode::Enforce);
+
+        assert!(cx.check_capability(Capability::BlockingPool).is_ok());
+        let err = cx
+            .check_capability(Capability::Process)
+            .expect_err("undeclared capability is denied");
+        assert_eq!(err.capability(), Capability::Process);
+        assert_eq!(err.site(), "capability-manifest:svc");
+        assert!(!err.is_widening());
+
+        {
+            let _guard = Cx::set_current(Some(cx.clone()));
+            let err = check_ambient_io(Capability::Net).expect_err("ambient use is denied");
+            assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        }
+        assert_eq!(
+            audit.observed(),
+            [Capability::BlockingPool, Capability::Process, Capability::Net]
+                .into_iter()
+                .collect::<CapabilitySet>()
+        );
+    }
+
+    #[test]
+    fn restriction_denials_take_precedence_over_the_audit() {
+        let (cx, audit) = audited(CapabilitySet::all(), CapabilityAuditMode::Enforce);
+        let restricted = cx
+            .restrict_capabilities(CapabilitySet::none(), "plugin")
+            .expect("narrowing succeeds");
+
+        let err = restricted
+            .check_capability(Capability::Net)
+            .expect_err("restriction denies");
+        assert_eq!(err.site(), "plugin");
+        assert!(audit.uses().is_empty(), "refused uses are not recorded");
+    }
+
+    #[test]
+    fn usage_log_names_member_mode_and_uses() {
+        let (cx, audit) = audited(CapabilitySet::none(), CapabilityAuditMode::Observe);
+        assert!(cx.check_capability(Capability::RemoteSpawn).is_ok());
+
+        let log: serde_json::Value =
+            serde_json::from_str(&audit.usage_log_json()).expect("usage log is JSON");
+        assert_eq!(log["schema_version"], CAPABILITY_USAGE_LOG_SCHEMA_VERSION);
+        assert_eq!(log["member"], "svc");
+        assert_eq!(log["mode"], "observe");
+        assert_eq!(log["uses"][0]["capability"], "remote_spawn");
+        assert_eq!(log["uses"][0]["declared"], false);
+        assert_eq!(log["uses"][0]["region"], cx.region_id().to_string());
+    }
+}
@@ -54,9 +54,13 @@
 //! - Framework can add domain-specific context
 //! - All capabilities flow through the wrapped Cx
 
+use super::attenuation::{Capability, CapabilityRestriction};
+use super::capability_audit::CapabilityAudit;
 use super::cap;
+use super::id_gen;
 use super::macaroon::{MacaroonToken, VerificationContext, VerificationError};
 use super::registry::RegistryHandle;
+use super::rng::{CxRng, DEFAULT_STREAM, RngStreams};
 use crate::combinator::select::SelectAll;
 use crate::evidence_sink::EvidenceSink;
 #[cfg(feature = "messaging-fabric")]
@@ -72,7 +76,8 @@ use crate::messaging::ir::CapabilityTokenSchema;
 #[cfg(feature = "messaging-fabric")]
 use crate::messaging::subject::SubjectPattern;
 use crate::observability::{
-    DiagnosticContext, LogCollector, LogEntry, ObservabilityConfig, SpanId,
+    DiagnosticContext, EventRateLimit, LogCollector, LogEntry, ObservabilityConfig, RegionLimit,
+    SpanId,
 };
 use crate::remote::RemoteCap;
 use crate::runtime::blocking_pool::BlockingPoolHandle;
@@ -90,7 +95,8 @@ use crate::tracing_compat::{debug, error, info, trace, warn};
 use crate::types::task_context::{CancelWaker, CancelWakerRegistration};
 use crate::types::{
     Budget, CancelKind, CancelReason, CapabilityBudget, CapabilityBudgetRefusal,
-    CapabilityBudgetRequirements, CxInner, RegionId, SystemPressure, TaskId, Time,
+    CapabilityBudgetRequirements, CxInner, DecisionId, RegionId, SystemPressure, TaskId, Time,
+    TraceId,
 };
 use crate::util::{ArenaIndex, EntropySource, OsEntropy};
 use std::cell::RefCell;
@@ -179,6 +185,9 @@ struct CxHandles {
     timer_driver: Option<TimerDriverHandle>,
     blocking_pool: Option<BlockingPoolHandle>,
     entropy: Arc<dyn EntropySource>,
+    /// Task random streams behind [`Cx::rng`]; replaced along with
+    /// `entropy` when a child task's context is derived.
+    rng_streams: Arc<RngStreams>,
     logical_clock: LogicalClockHandle,
     remote_cap: Option<Arc<RemoteCap>>,
     registry: Option<RegistryHandle>,
@@ -191,9 +200,18 @@ struct CxHandles {
     /// Pending-spawn counter for THIS Cx's region (cloned under the state
     /// lock at Cx build time; credits gate region close per A1.2).
     pending_spawns: Option<Arc<crate::record::region::PendingSpawnCounter>>,
+    /// Deferred-disposal slot for THIS Cx's region, drained by the runtime
+    /// when the region finalizes.
+    disposal: Option<Arc<crate::cx::dispose::RegionDisposalSlot>>,
     /// Runtime-scoped default HTTP client slot. It is lazy so Cx creation does
     /// not allocate a pool unless the high-level HTTP facade is used.
     default_http_client: DefaultHttpClientSlot,
+    /// How far a `timeout()` may overshoot the ambient deadline before its
+    /// clamp is reported as a misconfiguration.
+    deadline_clamp_slack: Duration,
+    /// Runtime-scoped capability-usage audit, shared by every task context
+    /// the runtime builds.
+    capability_audit: Option<Arc<CapabilityAudit>>,
     #[cfg(feature = "messaging-fabric")]
     fabric_capabilities: Arc<FabricCapabilityRegistry>,
 }
@@ -234,6 +252,9 @@ pub(crate) struct CancelWakerToken {
 #[derive(Debug)]
 pub struct Cx<Caps = cap::All> {
     pub(crate) inner: Arc<parking_lot::RwLock<CxInner>>,
+    /// `CxInner::fast_cancel`, cached so [`Cx::cancel_point`] reads it
+    /// without taking the context lock. The inner flag is never replaced.
+    pub(crate) fast_cancel: Arc<std::sync::atomic::AtomicBool>,
     observability: Arc<parking_lot::RwLock<ObservabilityState>>,
     handles: Arc<CxHandles>,
     /// br-asupersync-5ckssb: runtime capability mask. Mirrors the
@@ -251,6 +272,11 @@ pub struct Cx<Caps = cap::All> {
     /// runtime mask blocks the capability — this is the actual
     /// teeth of the ambient-authority defense.
     pub(crate) runtime_mask: cap::CapMask,
+    /// Runtime capability attenuation applied via
+    /// [`Cx::restrict_capabilities`]. Per-instance like `runtime_mask`:
+    /// restricting derives a new `Cx` without affecting clones of the
+    /// source. `None` (the common case) means unrestricted.
+    pub(crate) capability_restriction: Option<Arc<CapabilityRestriction>>,
     // Use fn() -> Caps instead of just Caps to ensure Send+Sync regardless of Caps
     _caps: PhantomData<fn() -> Caps>,
 }
@@ -262,9 +288,11 @@ impl<Caps> Clone for Cx<Caps> {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            fast_cancel: Arc::clone(&self.fast_cancel),
             observability: Arc::clone(&self.observability),
             handles: Arc::clone(&self.handles),
             runtime_mask: self.runtime_mask,
+            capability_restriction: self.capability_restriction.clone(),
             _caps: PhantomData,
         }
     }
@@ -482,6 +510,7 @@ pub struct ObservabilityState {
     trace: Option<TraceBufferHandle>,
     loser_drain_history: Option<LoserDrainHistoryHandle>,
     include_timestamps: bool,
+    event_rate_limit: Option<RegionLimit>,
 }
 
 impl ObservabilityState {
@@ -496,6 +525,7 @@ impl ObservabilityState {
             trace: None,
             loser_drain_history: None,
             include_timestamps: true,
+            event_rate_limit: None,
         }
     }
 
@@ -516,6 +546,7 @@ impl ObservabilityState {
             trace: None,
             loser_drain_history: None,
             include_timestamps: config.include_timestamps(),
+            event_rate_limit: None,
         }
     }
 
@@ -528,6 +559,7 @@ impl ObservabilityState {
             trace: self.trace.clone(),
             loser_drain_history: self.loser_drain_history.clone(),
             include_timestamps: self.include_timestamps,
+            event_rate_limit: self.event_rate_limit,
         }
     }
 }
@@ -903,12 +935,13 @@ impl<Caps> Cx<Caps> {
     /// Creates a new capability context from shared state (internal use).
     #[allow(dead_code)] // Internal construction path for runtime integration
     pub(crate) fn from_inner(inner: Arc<parking_lot::RwLock<CxInner>>) -> Self {
-        let (region, task) = {
+        let (region, task, fast_cancel) = {
             let guard = inner.read();
-            (guard.region, guard.task)
+            (guard.region, guard.task, Arc::clone(&guard.fast_cancel))
         };
         Self {
             inner,
+            fast_cancel,
             observability: Arc::new(parking_lot::RwLock::new(ObservabilityState::new(
                 region, task,
             ))),
@@ -918,6 +951,7 @@ impl<Caps> Cx<Caps> {
                 timer_driver: None,
                 blocking_pool: None,
                 entropy: Arc::new(OsEntropy),
+                rng_streams: Arc::default(),
                 logical_clock: LogicalClockHandle::default(),
                 remote_cap: None,
                 registry: None,
@@ -926,11 +960,15 @@ impl<Caps> Cx<Caps> {
                 macaroon: None,
                 spawn_gateway: None,
                 pending_spawns: None,
+                disposal: None,
                 default_http_client: DefaultHttpClientSlot::default(),
+                deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+                capability_audit: None,
                 #[cfg(feature = "messaging-fabric")]
                 fabric_capabilities: Arc::new(FabricCapabilityRegistry::default()),
             }),
             runtime_mask: cap::CapMask::all(),
+            capability_restriction: None,
             _caps: PhantomData,
         }
     }
@@ -995,7 +1033,9 @@ impl<Caps> Cx<Caps> {
         timer_driver: Option<TimerDriverHandle>,
         entropy: Option<Arc<dyn EntropySource>>,
     ) -> Self {
-        let inner = Arc::new(parking_lot::RwLock::new(CxInner::new(region, task, budget)));
+        let inner = CxInner::new(region, task, budget);
+        let fast_cancel = Arc::clone(&inner.fast_cancel);
+        let inner = Arc::new(parking_lot::RwLock::new(inner));
         let observability_state =
             observability.unwrap_or_else(|| ObservabilityState::new(region, task));
         let observability = Arc::new(parking_lot::RwLock::new(observability_state));
@@ -1014,6 +1054,7 @@ impl<Caps> Cx<Caps> {
 
         Self {
             inner,
+            fast_cancel,
             observability,
             handles: Arc::new(CxHandles {
                 io_driver,
@@ -1021,6 +1062,7 @@ impl<Caps> Cx<Caps> {
                 timer_driver,
                 blocking_pool: None,
                 entropy,
+                rng_streams: Arc::default(),
                 logical_clock: LogicalClockHandle::default(),
                 remote_cap: None,
                 registry: None,
@@ -1029,11 +1071,15 @@ impl<Caps> Cx<Caps> {
                 macaroon: None,
                 spawn_gateway: None,
                 pending_spawns: None,
+                disposal: None,
                 default_http_client: DefaultHttpClientSlot::default(),
+                deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+                capability_audit: None,
                 #[cfg(feature = "messaging-fabric")]
                 fabric_capabilities: Arc::new(FabricCapabilityRegistry::default()),
             }),
             runtime_mask: cap::CapMask::all(),
+            capability_restriction: None,
             _caps: PhantomData,
         }
     }
@@ -1067,6 +1113,39 @@ impl<Caps> Cx<Caps> {
         self
     }
 
+    /// Sets how far a [`timeout`](crate::time::timeout) may overshoot the
+    /// effective [`deadline`](Self::deadline) before clamping it is reported
+    /// as a warning. Runtime-built contexts take this from the runtime
+    /// configuration; spawned children inherit it.
+    #[must_use]
+    pub fn with_deadline_clamp_slack(mut self, slack: Duration) -> Self {
+        Arc::make_mut(&mut self.handles).deadline_clamp_slack = slack;
+        self
+    }
+
+    /// Returns the deadline clamp warning slack (see
+    /// [`with_deadline_clamp_slack`](Self::with_deadline_clamp_slack)).
+    #[inline]
+    #[must_use]
+    pub fn deadline_clamp_slack(&self) -> Duration {
+        self.handles.deadline_clamp_slack
+    }
+
+    /// Attaches a capability-usage audit. Runtime-built contexts take this
+    /// from the runtime configuration; spawned children inherit it.
+    #[must_use]
+    pub fn with_capability_audit(mut self, audit: Option<Arc<CapabilityAudit>>) -> Self {
+        Arc::make_mut(&mut self.handles).capability_audit = audit;
+        self
+    }
+
+    /// Returns the capability-usage audit attached to this context, if any.
+    #[inline]
+    #[must_use]
+    pub fn capability_audit(&self) -> Option<&Arc<CapabilityAudit>> {
+        self.handles.capability_audit.as_ref()
+    }
+
     /// Re-type this context to a narrower capability set.
     ///
     /// This is a zero-cost type-level restriction. It does not change runtime behavior,
@@ -1085,6 +1164,7 @@ impl<Caps> Cx<Caps> {
     pub(crate) fn retype<NewCaps>(&self) -> Cx<NewCaps> {
         Cx {
             inner: self.inner.clone(),
+            fast_cancel: self.fast_cancel.clone(),
             observability: self.observability.clone(),
             handles: self.handles.clone(),
             // br-asupersync-5ckssb: preserve the runtime mask across
@@ -1092,6 +1172,7 @@ impl<Caps> Cx<Caps> {
             // the runtime mask; widening is impossible at this layer
             // because the typed `restrict` requires SubsetOf.
             runtime_mask: self.runtime_mask,
+            capability_restriction: self.capability_restriction.clone(),
             _caps: PhantomData,
         }
     }
@@ -1320,6 +1401,31 @@ impl<Caps> Cx<Caps> {
         self
     }
 
+    /// Attaches this Cx's region deferred-disposal slot.
+    #[must_use]
+    pub(crate) fn with_disposal_slot(
+        mut self,
+        slot: Option<Arc<crate::cx::dispose::RegionDisposalSlot>>,
+    ) -> Self {
+        Arc::make_mut(&mut self.handles).disposal = slot;
+        self
+    }
+
+    /// Returns the [`DisposalQueue`](crate::cx::DisposalQueue) of this
+    /// context's region.
+    ///
+    /// The runtime drains the queue when the region finalizes. A context not
+    /// bound to a runtime region gets a sealed queue: guards dropped into it
+    /// record a [`DisposeLeak`](crate::cx::DisposeLeak).
+    #[must_use]
+    pub fn disposal_queue(&self) -> crate::cx::DisposalQueue {
+        let region = self.region_id();
+        self.handles.disposal.as_ref().map_or_else(
+            || crate::cx::dispose::RegionDisposalSlot::closed().queue(region),
+            |slot| slot.queue(region),
+        )
+    }
+
     /// Share the parent's lazy runtime-default HTTP client slot with this Cx.
     ///
     /// Child contexts inherit the slot rather than allocating their own, so
@@ -1921,6 +2027,16 @@ impl<Caps> Cx<Caps> {
         self.inner.read().task_type.clone()
     }
 
+    /// Returns the call site that spawned this task, if it was spawned
+    /// through [`Cx::spawn`] or [`Cx::spawn_in`].
+    ///
+    /// Resolve it to a name with a [`SymbolMap`](crate::trace::SymbolMap).
+    #[inline]
+    #[must_use]
+    pub fn spawn_site(&self) -> Option<crate::trace::SpawnSiteId> {
+        self.inner.read().spawn_site
+    }
+
     /// Sets a task type label for adaptive monitoring and metrics.
     ///
     /// This is intended to be called early in task execution to associate
@@ -1967,6 +2083,28 @@ impl<Caps> Cx<Caps> {
         inner.task_type = Some(task_type);
     }
 
+    /// Sets whether this task traps exit signals from linked tasks.
+    ///
+    /// A trapping task is not cancelled when a link partner terminates;
+    /// the termination is delivered to its designated exit mailbox as an
+    /// [`ExitSignal`](crate::link::ExitSignal) instead. Explicit kills are
+    /// never trapped. The runtime reads this flag each time a partner
+    /// completes (see [`RuntimeState::link_tasks`](crate::runtime::RuntimeState::link_tasks)),
+    /// so a change applies to the next exit.
+    ///
+    /// Returns the previous setting.
+    pub fn trap_exits(&self, trap: bool) -> bool {
+        let mut inner = self.inner.write();
+        std::mem::replace(&mut inner.trap_exits, trap)
+    }
+
+    /// Returns `true` if this task traps exit signals from linked tasks.
+    #[inline]
+    #[must_use]
+    pub fn traps_exits(&self) -> bool {
+        self.inner.read().trap_exits
+    }
+
     /// Returns the current budget.
     ///
     /// The budget defines resource limits for this task:
@@ -1994,6 +2132,49 @@ impl<Caps> Cx<Caps> {
         self.inner.read().budget
     }
 
+    /// Returns the effective deadline for work running under this context.
+    ///
+    /// This is the tightest of the budget deadline (scopes already clamp it to
+    /// their parent's, so it is the minimum along the scope chain and is
+    /// inherited by tasks spawned into the scope) and the deadline of every
+    /// enclosing [`timeout`](crate::time::timeout) currently polling this
+    /// task. `timeout` clamps itself to it, and libraries consult it to
+    /// decline work that cannot finish in time. `None` means unbounded.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(deadline) = cx.deadline() {
+    ///     if deadline <= cx.now() {
+    ///         return Err(Error::DeadlineExceeded);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn deadline(&self) -> Option<Time> {
+        let inner = self.inner.read();
+        match (inner.budget.deadline, inner.timeout_deadline) {
+            (Some(budget), Some(timeout)) => Some(budget.min(timeout)),
+            (budget, timeout) => budget.or(timeout),
+        }
+    }
+
+    /// Publishes an enclosing timeout deadline for the code polled until the
+    /// matching [`Self::leave_timeout_deadline`]. Returns the value to restore.
+    pub(crate) fn enter_timeout_deadline(&self, deadline: Time) -> Option<Time> {
+        let mut inner = self.inner.write();
+        let previous = inner.timeout_deadline;
+        inner.timeout_deadline = Some(previous.map_or(deadline, |outer| outer.min(deadline)));
+        previous
+    }
+
+    /// Restores the enclosing timeout deadline saved by
+    /// [`Self::enter_timeout_deadline`].
+    pub(crate) fn leave_timeout_deadline(&self, previous: Option<Time>) {
+        self.inner.write().timeout_deadline = previous;
+    }
+
     /// Returns the explicit capability/resource budget carried by this context.
     #[inline]
     #[must_use]
@@ -2830,6 +3011,42 @@ impl<Caps> Cx<Caps> {
         });
     }
 
+    /// Records a named marker event for lab ordering oracles.
+    ///
+    /// Markers are user trace events carrying a name, the recording task, and
+    /// structured fields; [`OrderingOracle`](crate::lab::oracle::OrderingOracle)
+    /// checks happens-before, mutual-exclusion, and occurrence rules over
+    /// them. Without a trace buffer only the log entry is emitted.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// db.commit(&key).await?;
+    /// cx.mark("db_commit", &[("key", &key)]);
+    /// ```
+    pub fn mark(&self, name: &str, fields: &[(&str, &str)]) {
+        self.log_if_collector(|| {
+            let mut entry = LogEntry::trace(format!("mark {name}"));
+            for &(k, v) in fields {
+                entry = entry.with_field(k, v);
+            }
+            entry
+        });
+        let Some(trace) = self.trace_buffer() else {
+            return;
+        };
+        let now = self
+            .handles
+            .timer_driver
+            .as_ref()
+            .map_or_else(wall_clock_now, TimerDriverHandle::now);
+        let message = crate::lab::oracle::ordering::encode_marker(name, self.task_id(), fields);
+        let logical_time = self.logical_tick();
+        trace.record_event(move |seq| {
+            TraceEvent::user_trace(seq, now, message).with_logical_time(logical_time)
+        });
+    }
+
     /// Enters a named span, returning a guard that ends the span on drop.
     ///
     /// The span forks the current `DiagnosticContext`, assigning a new
@@ -2885,24 +3102,49 @@ impl<Caps> Cx<Caps> {
             return;
         };
         let include_timestamps = obs.include_timestamps;
+        let event_rate_limit = obs.event_rate_limit;
         let context = obs.context.clone();
         drop(obs);
         let mut entry = build_entry().with_context(&context);
+        let unstamped = include_timestamps && entry.timestamp() == Time::ZERO;
+        let now = if unstamped || collector.rate_limiter().is_some() {
+            self.handles
+                .timer_driver
+                .as_ref()
+                .map_or_else(wall_clock_now, TimerDriverHandle::now)
+        } else {
+            entry.timestamp()
+        };
         // `LogEntry::new`/`info` initialize `timestamp` to `Time::ZERO`, which is
         // the "unset" sentinel: when the caller did not supply an explicit
         // timestamp we fill it in from the context's timer driver. The previous
         // sentinel (`Time::from_nanos(1_000_000_000)`) never matched the actual
         // default, so auto-timestamping silently never fired and entries kept
         // `Time::ZERO`.
-        if include_timestamps && entry.timestamp() == Time::ZERO {
-            let now = self
-                .handles
-                .timer_driver
-                .as_ref()
-                .map_or_else(wall_clock_now, TimerDriverHandle::now);
+        if unstamped {
             entry = entry.with_timestamp(now);
         }
-        collector.log(entry);
+        collector.log_scoped(entry, event_rate_limit, now);
+    }
+
+    /// Rate-limits entries logged from this region's subtree with `limit`,
+    /// overriding the collector's default limit.
+    ///
+    /// The limit applies to this task and to tasks it spawns afterwards; the
+    /// whole subtree shares one bucket per (spawn site, event name), so an
+    /// untrusted tenant region can be held to a tighter budget than the rest
+    /// of the runtime. Use [`EventRateLimit::UNLIMITED`] to exempt a region.
+    /// Has no effect unless the collector has a rate limiter.
+    pub fn set_event_rate_limit(&self, limit: EventRateLimit) {
+        let region = self.region_id();
+        let mut obs = self.observability.write();
+        obs.event_rate_limit = Some(RegionLimit { region, limit });
+    }
+
+    /// Returns the region event rate limit in effect, if any.
+    #[must_use]
+    pub fn event_rate_limit(&self) -> Option<RegionLimit> {
+        self.observability.read().event_rate_limit
     }
 
     /// Returns a snapshot of the current diagnostic context.
@@ -3071,6 +3313,82 @@ impl<Caps> Cx<Caps> {
         }
     }
 
+    /// Returns this task's default deterministic random stream.
+    ///
+    /// Use this instead of `rand::thread_rng()` for application randomness:
+    /// under the lab the sequence is derived from the lab seed and the
+    /// task's identity, so a replay draws the same values. In production it
+    /// is seeded from OS entropy unless
+    /// [`RuntimeBuilder::rng_seed`](crate::runtime::RuntimeBuilder::rng_seed)
+    /// fixes it. Equivalent to `rng_named("default")`; see [`super::rng`].
+    #[must_use]
+    pub fn rng(&self) -> CxRng
+    where
+        Caps: cap::HasRandom,
+    {
+        self.rng_named(DEFAULT_STREAM)
+    }
+
+    /// Returns this task's random stream called `name`.
+    ///
+    /// Each name is an independent sequence: draws from one stream never
+    /// shift another, so subsystems can add or remove draws without
+    /// perturbing each other's replays.
+    #[must_use]
+    pub fn rng_named(&self, name: &str) -> CxRng
+    where
+        Caps: cap::HasRandom,
+    {
+        let handles = &self.handles;
+        let stream = handles.rng_streams.stream(name, handles.entropy.as_ref(), self.task_id());
+        CxRng::new(stream, self.trace_buffer(), handles.timer_driver.clone())
+    }
+
+    /// Generates the next time-ordered [`TraceId`] for this context.
+    ///
+    /// In the lab runtime the id is derived from the virtual clock and the
+    /// seeded entropy source, so replaying a seed replays the id sequence.
+    /// In production the timestamp is non-decreasing Unix milliseconds and
+    /// the random bits come from OS entropy. Runtime code must mint ids
+    /// through this method rather than from ambient time or randomness.
+    #[must_use]
+    pub fn next_trace_id(&self) -> TraceId
+    where
+        Caps: cap::HasRandom,
+    {
+        let (ts_ms, random) = self.next_id_parts();
+        TraceId::from_parts(ts_ms, random)
+    }
+
+    /// Generates the next time-ordered [`DecisionId`] for this context.
+    ///
+    /// Shares the clock and entropy policy of [`Self::next_trace_id`].
+    #[must_use]
+    pub fn next_decision_id(&self) -> DecisionId
+    where
+        Caps: cap::HasRandom,
+    {
+        let (ts_ms, random) = self.next_id_parts();
+        DecisionId::from_parts(ts_ms, random)
+    }
+
+    /// Timestamp and random bits for the next capability-minted id.
+    fn next_id_parts(&self) -> (u64, u128) {
+        let entropy = &self.handles.entropy;
+        let ts_ms = if entropy.source_id() == id_gen::DETERMINISTIC_SOURCE_ID {
+            // Never consult the wall clock under deterministic entropy: a
+            // context without a timer driver stamps ids at the epoch.
+            self.handles
+                .timer_driver
+                .as_ref()
+                .map_or(0, |timer| timer.now().as_millis())
+        } else {
+            crate::time::unix_time_millis()
+        };
+        let random = id_gen::random_bits(entropy.next_u64(), entropy.next_u64());
+        (ts_ms, random)
+    }
+
     /// Sets the cancellation flag (internal use).
     #[allow(dead_code)]
     pub(crate) fn set_cancel_internal(&self, value: bool) {
@@ -3983,8 +4301,8 @@ where
     /// admission with the canonical task identity, then overlaid with this
     /// parent's inherited capabilities (observability fork, entropy fork,
     /// io-cap/registry/remote/blocking/evidence/macaroon/pressure handles,
-    /// capability budget, runtime mask) — the same inheritance set as
-    /// `Scope::build_child_task_cx`.
+    /// capability budget, runtime mask, capability restriction) — the same
+    /// inheritance set as `Scope::build_child_task_cx`.
     ///
     /// # Targeting: `Cx::spawn` vs `Cx::spawn_in` vs state-threaded boot
     ///
@@ -4003,6 +4321,7 @@ where
     /// runtime wiring). Admission-time denials (region closing, quota)
     /// resolve through the returned handle as `JoinError::Cancelled`.
     /// Never panics.
+    #[track_caller]
     pub fn spawn<F, Fut>(
         &self,
         f: F,
@@ -4024,6 +4343,7 @@ where
             self.capability_budget(),
             &gateway,
             &pending,
+            crate::trace::SpawnSiteId::caller(),
             f,
         )
     }
@@ -4066,6 +4386,11 @@ where
     /// differs from this context's). Admission-time denials (region
     /// closing, quota) resolve through the returned handle as
     /// `JoinError::Cancelled`. Never panics.
+    ///
+    /// Returns [`SpawnError::CapabilityDenied`] when `scope` targets another
+    /// region and this context was derived with [`Cx::restrict_capabilities`]
+    /// without [`Capability::CrossRegionSpawn`].
+    #[track_caller]
     pub fn spawn_in<F, Fut, P>(
         &self,
         scope: &crate::cx::Scope<'_, P>,
@@ -4077,6 +4402,7 @@ where
         Fut: Future + Send + 'static,
         Fut::Output: Send + 'static,
     {
+        self.check_spawn_region(scope.region_id())?;
         let Some(gateway) = self.spawn_gateway_handle() else {
             return Err(crate::runtime::state::SpawnError::RuntimeUnavailable);
         };
@@ -4097,6 +4423,7 @@ where
             scope.capability_budget(),
             &gateway,
             &pending,
+            crate::trace::SpawnSiteId::caller(),
             f,
         )
     }
@@ -4167,6 +4494,9 @@ where
     /// spawn gateway or region counter. Admission-time denials (region
     /// closing, quota) resolve through the returned handle as
     /// `JoinError::Cancelled`. Never panics.
+    ///
+    /// Returns [`SpawnError::CapabilityDenied`] when this context was derived
+    /// with [`Cx::restrict_capabilities`] without [`Capability::BlockingPool`].
     pub fn spawn_blocking<F, R>(
         &self,
         f: F,
@@ -4175,6 +4505,7 @@ where
         F: FnOnce(Cx<Caps>) -> R + Send + 'static,
         R: Send + 'static,
     {
+        self.check_capability(Capability::BlockingPool)?;
         let pool = self.blocking_pool_handle();
         self.spawn(move |child| async move {
             match pool {
@@ -4187,6 +4518,56 @@ where
         })
     }
 
+    /// Spawns a blocking closure onto the blocking lane for `kind`.
+    ///
+    /// Like [`Cx::spawn_blocking`] (which always uses
+    /// [`BlockingKind::Io`](crate::runtime::BlockingKind::Io)), the closure runs
+    /// inside a task admitted to this context's region, so region close waits
+    /// for it regardless of lane. When the runtime has no dedicated CPU lane,
+    /// both kinds share one pool; without any pool the closure runs inline.
+    ///
+    /// If the lane's queue is full, its
+    /// [`BlockingQueueFullPolicy`](crate::runtime::BlockingQueueFullPolicy)
+    /// decides: `Wait` yields until capacity frees up, `Reject` resolves the
+    /// handle with [`BlockingSpawnError::QueueFull`](crate::runtime::BlockingSpawnError::QueueFull).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpawnError::RuntimeUnavailable`] when this Cx carries no
+    /// spawn gateway or region counter. Admission-time denials resolve
+    /// through the returned handle as `JoinError::Cancelled`. Never panics.
+    ///
+    /// Returns [`SpawnError::CapabilityDenied`] when this context was derived
+    /// with [`Cx::restrict_capabilities`] without [`Capability::BlockingPool`].
+    pub fn spawn_blocking_kind<F, R>(
+        &self,
+        kind: crate::runtime::BlockingKind,
+        f: F,
+    ) -> Result<
+        crate::runtime::TaskHandle<Result<R, crate::runtime::BlockingSpawnError>>,
+        crate::runtime::state::SpawnError,
+    >
+    where
+        F: FnOnce(Cx<Caps>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.check_capability(Capability::BlockingPool)?;
+        let pool = self.blocking_pool_handle();
+        self.spawn(move |child| async move {
+            match pool {
+                Some(pool) => {
+                    crate::runtime::spawn_blocking::spawn_blocking_kind_on_pool(
+                        pool,
+                        kind,
+                        move || f(child),
+                    )
+                    .await
+                }
+                None => Ok(f(child)),
+            }
+        })
+    }
+
     /// Spawns a blocking closure into **`scope`'s region** without touching
     /// the `RuntimeState` lock (br-asupersync-wwyi9k / A2.2b).
     ///
@@ -4201,6 +4582,11 @@ where
     /// spawn gateway, or when `scope` carries no pending-spawn counter for
     /// its region (see [`Cx::spawn_in`]). Admission-time denials resolve
     /// through the returned handle as `JoinError::Cancelled`. Never panics.
+    ///
+    /// Returns [`SpawnError::CapabilityDenied`] when this context was derived
+    /// with [`Cx::restrict_capabilities`] without [`Capability::BlockingPool`],
+    /// or without [`Capability::CrossRegionSpawn`] when `scope` targets
+    /// another region.
     pub fn spawn_blocking_in<F, R, P>(
         &self,
         scope: &crate::cx::Scope<'_, P>,
@@ -4211,6 +4597,7 @@ where
         F: FnOnce(Cx<Caps>) -> R + Send + 'static,
         R: Send + 'static,
     {
+        self.check_capability(Capability::BlockingPool)?;
         let pool = self.blocking_pool_handle();
         self.spawn_in(scope, move |child| async move {
             match pool {
@@ -4286,6 +4673,10 @@ where
     /// As [`Cx::spawn_local`], plus
     /// [`SpawnError::RuntimeUnavailable`] when `scope` carries no
     /// pending-spawn counter for its region (see [`Cx::spawn_in`]).
+    ///
+    /// Returns [`SpawnError::CapabilityDenied`] when `scope` targets another
+    /// region and this context was derived with [`Cx::restrict_capabilities`]
+    /// without [`Capability::CrossRegionSpawn`].
     pub fn spawn_local_in<F, Fut, P>(
         &self,
         scope: &crate::cx::Scope<'_, P>,
@@ -4297,6 +4688,7 @@ where
         Fut: Future + 'static,
         Fut::Output: Send + 'static,
     {
+        self.check_spawn_region(scope.region_id())?;
         let Some(gateway) = self.spawn_gateway_handle() else {
             return Err(crate::runtime::state::SpawnError::RuntimeUnavailable);
         };
@@ -4318,6 +4710,20 @@ where
         )
     }
 
+    /// Refuses a spawn into another region when this context's capability
+    /// restriction withholds [`Capability::CrossRegionSpawn`], and reports
+    /// the spawn to the capability audit if one is installed.
+    fn check_spawn_region(
+        &self,
+        region: RegionId,
+    ) -> Result<(), crate::runtime::state::SpawnError> {
+        let audited = self.capability_restriction.is_some() || self.capability_audit().is_some();
+        if audited && region != self.region_id() {
+            self.check_capability(Capability::CrossRegionSpawn)?;
+        }
+        Ok(())
+    }
+
     /// Producer-side machinery for the owner-pinned local spawn lane:
     /// `!Send` mirror of [`Self::spawn_via_gateway`]. Fails closed with
     /// `LocalSchedulerUnavailable` off-worker (the thread-local lane is
@@ -4359,6 +4765,14 @@ where
             gateway,
         )));
 
+        let provisional = gateway.mailbox().allocate_task_id();
+        let handle = crate::runtime::TaskHandle::new_pending(
+            provisional,
+            result_rx,
+            Arc::clone(&admitted_slot),
+        );
+        let completion = handle.completion_guard();
+
         let parent = self.clone();
         let factory_tx = Arc::clone(&shared_tx);
         let factory: LocalSpawnFactoryFn = Box::new(move |admission_cx: Cx| {
@@ -4367,6 +4781,7 @@ where
             // all user/reentrant hooks stay inside the caught future below.
             let completion_cx = admission_cx.retype::<cap::All>();
             Box::pin(async move {
+                completion.start();
                 match (crate::cx::scope::CatchUnwind {
                     inner: async move {
                         let task_id = admission_cx.task_id();
@@ -4383,12 +4798,13 @@ where
                 .await
                 {
                     Ok((value, child_all)) => {
+                        let result = completion.finish(Ok(value));
                         if let Some(tx) = factory_tx
                             .lock()
                             .unwrap_or_else(std::sync::PoisonError::into_inner)
                             .take()
                         {
-                            let _ = tx.send(&child_all, Ok(value));
+                            let _ = tx.send(&child_all, result);
                         }
                         crate::types::Outcome::Ok(())
                     }
@@ -4396,15 +4812,14 @@ where
                         let message = crate::cx::scope::payload_to_string(&payload);
                         std::mem::forget(payload);
                         let panic_payload = crate::types::outcome::PanicPayload::new(message);
+                        let result =
+                            completion.finish(Err(JoinError::Panicked(panic_payload.clone())));
                         if let Some(tx) = factory_tx
                             .lock()
                             .unwrap_or_else(std::sync::PoisonError::into_inner)
                             .take()
                         {
-                            let _ = tx.send(
-                                &completion_cx,
-                                Err(JoinError::Panicked(panic_payload.clone())),
-                            );
+                            let _ = tx.send(&completion_cx, result);
                         }
                         crate::types::Outcome::Panicked(panic_payload)
                     }
@@ -4414,7 +4829,6 @@ where
 
         let cancel_tx = Arc::clone(&shared_tx);
         let error_tx = Arc::clone(&shared_tx);
-        let provisional = gateway.mailbox().allocate_task_id();
         let request = LocalSpawnRequest {
             task_id: provisional,
             region,
@@ -4441,7 +4855,7 @@ where
                 }
             })),
             pending_reservation: Some(pending.reserve()),
-            admitted_slot: Some(Arc::clone(&admitted_slot)),
+            admitted_slot: Some(admitted_slot),
         };
 
         if let Some(trace) = self.trace_buffer() {
@@ -4449,7 +4863,6 @@ where
             trace
                 .record_event(|seq| TraceEvent::task_spawn_enqueued(seq, now, provisional, region));
         }
-        let handle = crate::runtime::TaskHandle::new_pending(provisional, result_rx, admitted_slot);
         crate::runtime::spawn_mailbox::enqueue_local_spawn(request);
         Ok(handle)
     }
@@ -4459,7 +4872,9 @@ where
     /// [`SpawnRequest`](crate::runtime::spawn_mailbox::SpawnRequest) on the
     /// gateway with a pending reservation against `pending`, a take-once
     /// result channel, and cancel/admission-error slots, returning a
-    /// pending [`TaskHandle`](crate::runtime::TaskHandle).
+    /// pending [`TaskHandle`](crate::runtime::TaskHandle). `site` is stamped
+    /// on the child's context and the trace sidecar when admission runs it.
+    #[allow(clippy::too_many_arguments)]
     fn spawn_via_gateway<F, Fut>(
         &self,
         region: RegionId,
@@ -4467,6 +4882,7 @@ where
         capability_budget: crate::types::CapabilityBudget,
         gateway: &Arc<crate::runtime::spawn_mailbox::SpawnGateway>,
         pending: &Arc<crate::record::region::PendingSpawnCounter>,
+        site: crate::trace::SpawnSiteId,
         f: F,
     ) -> Result<crate::runtime::TaskHandle<Fut::Output>, crate::runtime::state::SpawnError>
     where
@@ -4491,15 +4907,28 @@ where
             gateway,
         )));
 
+        let provisional = gateway.mailbox().allocate_task_id();
+        let handle = crate::runtime::TaskHandle::new_pending(
+            provisional,
+            result_rx,
+            Arc::clone(&admitted_slot),
+        );
+        let completion = handle.completion_guard();
+
         // Parent snapshot for capability inheritance (cheap Arc clones).
         let parent = self.clone();
         let factory_tx = Arc::clone(&shared_tx);
         let factory: SpawnFactoryFn = Box::new(move |admission_cx: Cx| {
+            admission_cx.inner.write().spawn_site = Some(site);
+            if let Some(trace) = admission_cx.trace_buffer() {
+                trace.record_spawn_site(admission_cx.task_id(), site);
+            }
             // Keep a context for terminal result delivery even if inheritance
             // itself panics. Retyping only clones the admission-built handles;
             // all user/reentrant hooks stay inside the caught future below.
             let completion_cx = admission_cx.retype::<cap::All>();
             Box::pin(async move {
+                completion.start();
                 match (crate::cx::scope::CatchUnwind {
                     inner: async move {
                         let task_id = admission_cx.task_id();
@@ -4516,12 +4945,13 @@ where
                 .await
                 {
                     Ok((value, child_all)) => {
+                        let result = completion.finish(Ok(value));
                         if let Some(tx) = factory_tx
                             .lock()
                             .unwrap_or_else(std::sync::PoisonError::into_inner)
                             .take()
                         {
-                            let _ = tx.send(&child_all, Ok(value));
+                            let _ = tx.send(&child_all, result);
                         }
                         crate::types::Outcome::Ok(())
                     }
@@ -4529,15 +4959,14 @@ where
                         let message = crate::cx::scope::payload_to_string(&payload);
                         std::mem::forget(payload);
                         let panic_payload = crate::types::outcome::PanicPayload::new(message);
+                        let result =
+                            completion.finish(Err(JoinError::Panicked(panic_payload.clone())));
                         if let Some(tx) = factory_tx
                             .lock()
                             .unwrap_or_else(std::sync::PoisonError::into_inner)
                             .take()
                         {
-                            let _ = tx.send(
-                                &completion_cx,
-                                Err(JoinError::Panicked(panic_payload.clone())),
-                            );
+                            let _ = tx.send(&completion_cx, result);
                         }
                         crate::types::Outcome::Panicked(panic_payload)
                     }
@@ -4547,9 +4976,8 @@ where
 
         let cancel_tx = Arc::clone(&shared_tx);
         let error_tx = Arc::clone(&shared_tx);
-        let provisional = gateway.mailbox().allocate_task_id();
         let request = SpawnRequest::new_with_factory(provisional, region, budget, factory)
-            .with_admitted_slot(Arc::clone(&admitted_slot))
+            .with_admitted_slot(admitted_slot)
             .with_pending_reservation(pending.reserve())
             .with_unadmitted_cancel(Box::new(move |reason| {
                 if let Some(tx) = cancel_tx
@@ -4572,7 +5000,6 @@ where
                 }
             }));
 
-        let handle = crate::runtime::TaskHandle::new_pending(provisional, result_rx, admitted_slot);
         gateway.enqueue_and_notify(request)?;
         Ok(handle)
     }
@@ -4584,11 +5011,13 @@ where
     /// parent; `capability_budget` (the spawn target's planned envelope —
     /// the parent cx's for `Cx::spawn`, the SCOPE's for `Cx::spawn_in`,
     /// matching the state-threaded scope path, which applied the scope's;
-    /// br-asupersync-4onmas) and the parent runtime mask apply.
-    /// State-side wiring (drivers, logical clock, trace buffer, loser-drain
-    /// history, spawn gateway, region counter) stays as admission built it.
-    /// The shared `CxInner` is untouched, so cancellation and budget flow
-    /// through the record linkage admission already established.
+    /// br-asupersync-4onmas), the parent runtime mask, and the parent
+    /// capability restriction apply. State-side wiring (drivers, logical
+    /// clock, trace buffer, loser-drain history, spawn gateway, region
+    /// counter) stays as admission built it. The shared `CxInner` is
+    /// untouched apart from recording an inherited capability restriction,
+    /// so cancellation and budget flow through the record linkage admission
+    /// already established.
     pub(crate) fn overlay_parent_inheritance<PCaps, Out>(
         mut self,
         parent: &Cx<PCaps>,
@@ -4600,6 +5029,7 @@ where
         {
             let handles = Arc::make_mut(&mut self.handles);
             handles.entropy = parent.child_entropy(task_id);
+            handles.rng_streams = Arc::default();
             handles.io_cap = parent.io_cap_handle();
             handles.registry = parent.registry_handle();
             handles.remote_cap = parent.remote_cap_handle();
@@ -4607,6 +5037,7 @@ where
             handles.evidence_sink = parent.evidence_sink_handle();
             handles.macaroon = parent.macaroon_handle();
             handles.default_http_client = parent.handles.default_http_client.clone();
+            handles.deadline_clamp_slack = parent.handles.deadline_clamp_slack;
             if let Some(pressure) = parent.pressure_handle() {
                 handles.pressure = Some(pressure);
             }
@@ -4617,6 +5048,8 @@ where
         );
         let mut typed = self.retype::<Out>();
         typed.runtime_mask = parent.runtime_mask;
+        typed.capability_restriction = parent.capability_restriction.clone();
+        typed.record_capability_restriction();
         typed
     }
 }
@@ -5555,6 +5988,69 @@ mod tests {
         assert_eq!(cx2.random_u64(), cx3.random_u64());
     }
 
+    fn lab_id_cx(seed: u64) -> (Cx<cap::All>, Arc<crate::time::VirtualClock>) {
+        let clock = Arc::new(crate::time::VirtualClock::starting_at(Time::from_millis(
+            1_700_000_000_000,
+        )));
+        let cx = Cx::new_with_drivers(
+            RegionId::new_for_test(0, 1),
+            TaskId::new_for_test(0, 0),
+            Budget::INFINITE,
+            None,
+            None,
+            None,
+            Some(TimerDriverHandle::with_virtual_clock(Arc::clone(&clock))),
+            Some(Arc::new(DetEntropy::new(seed))),
+        );
+        (cx, clock)
+    }
+
+    fn lab_id_sequence(seed: u64) -> Vec<(TraceId, DecisionId)> {
+        let (cx, clock) = lab_id_cx(seed);
+        (0..16)
+            .map(|_| {
+                clock.advance(1_000_000);
+                (cx.next_trace_id(), cx.next_decision_id())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn next_ids_replay_identically_for_same_seed() {
+        let first = lab_id_sequence(42);
+        let second = lab_id_sequence(42);
+        assert_eq!(first, second);
+
+        // Lab ids are stamped from the virtual clock, not the wall clock.
+        assert_eq!(first[0].0.timestamp_ms(), 1_700_000_000_001);
+        assert!(
+            first
+                .windows(2)
+                .all(|pair| pair[0].0.timestamp_ms() < pair[1].0.timestamp_ms())
+        );
+    }
+
+    #[test]
+    fn next_ids_differ_across_seeds() {
+        let a = lab_id_sequence(1);
+        let b = lab_id_sequence(2);
+        assert!(a.iter().all(|ids| !b.contains(ids)));
+    }
+
+    #[test]
+    fn next_ids_are_time_ordered_in_native_mode() {
+        let cx = test_cx();
+        let ids: Vec<TraceId> = (0..256).map(|_| cx.next_trace_id()).collect();
+        assert!(
+            ids.windows(2)
+                .all(|pair| pair[0].timestamp_ms() <= pair[1].timestamp_ms())
+        );
+        // Native ids carry Unix milliseconds, not process-relative time.
+        assert!(ids[0].timestamp_ms() > 1_600_000_000_000);
+        let unique: std::collections::HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
     // ========================================================================
     // Cancel Attribution API Tests
     // ========================================================================
@@ -0,0 +1,1007 @@
+//! Async disposal protocol for resources that need awaited cleanup.
+//!
+//! Rust has no async `Drop`: a connection, transaction or TLS stream dropped
+//! without its `close().await` simply skips the cleanup. This module makes
+//! that omission recoverable and visible.
+//!
+//! - [`AsyncDispose`] is implemented by resources whose cleanup must be
+//!   awaited. Disposal is cancel-masked and bounded by a [`Budget`].
+//! - [`Disposer`] guards a value. The well-behaved path calls
+//!   [`Disposer::dispose`]; if the guard is dropped instead, the value is
+//!   handed to the owning region's [`DisposalQueue`] and disposed, awaited,
+//!   during region close.
+//! - Every region owns a lazily created queue. [`Disposer::in_region`] binds
+//!   a guard to the region the calling task runs in; the runtime drains that
+//!   queue as an async finalizer when the region enters finalization, with no
+//!   registration step on the caller's side.
+//! - Every implicit disposal emits a [`DisposalEvent::Implicit`] naming the
+//!   [`DisposalSite`] where the guard was constructed, so the forgotten
+//!   `dispose().await` shows up in logs and tests.
+//!
+//! # Leaks
+//!
+//! A queue is sealed once its region has finalized. A guard that outlives
+//! its region (for example, moved into a detached thread) can no longer
+//! defer its cleanup; dropping it records a typed [`DisposeLeak`] instead.
+//! [`Disposer::check_leak`] reports the same condition before the drop.
+//!
+//! # Owned resources only
+//!
+//! Deferred disposal moves the value into a region finalizer, so a guarded
+//! value must be `'static`. Borrowing handles such as database or Kafka
+//! transactions (`Transaction<'_>`) cannot be guarded; finish them with
+//! `commit`/`rollback` inside the borrow, and guard the owning connection.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut conn = Disposer::in_region(PgConnection::connect(cx, url).await?, cx);
+//! conn.get_mut().expect("live").execute(cx, "SELECT 1", &[]).await?;
+//! conn.dispose(cx).await; // or drop: cleanup still runs at region close
+//! ```
+
+use crate::cx::Cx;
+use crate::cx::scope::{CatchUnwind, payload_to_string};
+use crate::record::finalizer::finalizer_budget;
+use crate::tracing_compat::warn;
+use crate::types::{Budget, Outcome, RegionId};
+use parking_lot::Mutex;
+use std::fmt;
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Result of disposing a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisposeOutcome {
+    /// Cleanup ran to completion.
+    Completed,
+    /// Cleanup ran but reported an error.
+    Failed(String),
+    /// Cleanup exhausted its budget before finishing.
+    TimedOut,
+    /// Cleanup panicked; the panic was contained.
+    Panicked(String),
+    /// The value had already been disposed; nothing ran.
+    AlreadyDisposed,
+}
+
+impl DisposeOutcome {
+    /// Returns `true` if the resource is known to be released.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        matches!(self, Self::Completed | Self::AlreadyDisposed)
+    }
+
+    /// Maps a fallible cleanup result onto a disposal outcome.
+    #[must_use]
+    pub fn from_result<E: fmt::Display>(result: Result<(), E>) -> Self {
+        result.map_or_else(|err| Self::Failed(err.to_string()), |()| Self::Completed)
+    }
+
+    /// Maps a four-valued cleanup outcome onto a disposal outcome.
+    #[must_use]
+    pub fn from_outcome<E: fmt::Display>(outcome: Outcome<(), E>) -> Self {
+        match outcome {
+            Outcome::Ok(()) => Self::Completed,
+            Outcome::Err(err) => Self::Failed(err.to_string()),
+            Outcome::Cancelled(reason) => Self::Failed(format!("cancelled: {reason}")),
+            Outcome::Panicked(payload) => Self::Panicked(payload.message().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for DisposeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Completed => f.write_str("completed"),
+            Self::Failed(err) => write!(f, "failed: {err}"),
+            Self::TimedOut => f.write_str("timed out"),
+            Self::Panicked(message) => write!(f, "panicked: {message}"),
+            Self::AlreadyDisposed => f.write_str("already disposed"),
+        }
+    }
+}
+
+/// A resource whose cleanup must be awaited.
+///
+/// Implementations should release the resource and report how that went;
+/// they must not assume they run on the task that created the value. The
+/// protocol drives `dispose` under a cancel mask and a [`Budget`], so a
+/// `cx.checkpoint()` inside cleanup does not abort it early.
+pub trait AsyncDispose: Send + Sized {
+    /// Releases the resource.
+    fn dispose(self, cx: &Cx) -> impl Future<Output = DisposeOutcome> + Send;
+}
+
+/// Where a [`Disposer`] was constructed, and for which type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisposalSite {
+    type_name: &'static str,
+    location: &'static Location<'static>,
+}
+
+impl DisposalSite {
+    /// Returns the disposed value's type name.
+    #[must_use]
+    pub const fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the source location of the guard's construction.
+    #[must_use]
+    pub const fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl fmt::Display for DisposalSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` created at {}", self.type_name, self.location)
+    }
+}
+
+/// A [`Disposer`] outlived its region, so its cleanup never ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisposeLeak {
+    /// The region the guard was bound to.
+    pub region: RegionId,
+    /// Where the guard was constructed.
+    pub site: DisposalSite,
+}
+
+impl fmt::Display for DisposeLeak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} escaped region {:?} before disposal; cleanup did not run",
+            self.site, self.region
+        )
+    }
+}
+
+impl std::error::Error for DisposeLeak {}
+
+/// Observable disposal activity on a [`DisposalQueue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisposalEvent {
+    /// A guard was dropped without explicit disposal; cleanup was deferred
+    /// to region close.
+    Implicit {
+        /// The owning region.
+        region: RegionId,
+        /// Where the guard was constructed.
+        site: DisposalSite,
+    },
+    /// A deferred disposal ran during region close.
+    Finalized {
+        /// The owning region.
+        region: RegionId,
+        /// Where the guard was constructed.
+        site: DisposalSite,
+        /// How cleanup went.
+        outcome: DisposeOutcome,
+    },
+    /// A guard was dropped after its region finished finalizing.
+    Leaked(DisposeLeak),
+}
+
+type DeferredDispose =
+    Box<dyn FnOnce(Cx) -> Pin<Box<dyn Future<Output = DisposeOutcome> + Send>> + Send>;
+
+struct Deferred {
+    site: DisposalSite,
+    budget: Budget,
+    run: DeferredDispose,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: Vec<Deferred>,
+    events: Vec<DisposalEvent>,
+    sealed: bool,
+}
+
+struct QueueInner {
+    region: RegionId,
+    state: Mutex<QueueState>,
+}
+
+/// Per-region set of disposals deferred to the region's finalize phase.
+///
+/// [`Cx::disposal_queue`] returns the queue the runtime drains for the
+/// task's region; [`Scope::disposal_queue`] registers a separate one for
+/// embedders driving the region by hand. Deferred disposals run in LIFO
+/// order, each under its own budget.
+///
+/// [`Scope::disposal_queue`]: crate::cx::Scope::disposal_queue
+#[derive(Clone)]
+pub struct DisposalQueue {
+    inner: Arc<QueueInner>,
+}
+
+impl DisposalQueue {
+    /// Creates an unattached queue for `region`.
+    ///
+    /// Nothing drains an unattached queue until [`drain`](Self::drain) is
+    /// called; embedders that drive region close themselves use this.
+    #[must_use]
+    pub fn new(region: RegionId) -> Self {
+        Self {
+            inner: Arc::new(QueueInner {
+                region,
+                state: Mutex::new(QueueState::default()),
+            }),
+        }
+    }
+
+    /// Returns the owning region.
+    #[must_use]
+    pub fn region(&self) -> RegionId {
+        self.inner.region
+    }
+
+    /// Returns the number of disposals waiting for region close.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.inner.state.lock().pending.len()
+    }
+
+    /// Returns `true` once the queue has been drained by region close.
+    #[must_use]
+    pub fn is_sealed(&self) -> bool {
+        self.inner.state.lock().sealed
+    }
+
+    /// Returns every disposal event recorded so far, oldest first.
+    #[must_use]
+    pub fn events(&self) -> Vec<DisposalEvent> {
+        self.inner.state.lock().events.clone()
+    }
+
+    /// Returns the guards that escaped this queue's region.
+    #[must_use]
+    pub fn leaks(&self) -> Vec<DisposeLeak> {
+        self.inner
+            .state
+            .lock()
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                DisposalEvent::Leaked(leak) => Some(leak.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Runs every deferred disposal, then seals the queue.
+    ///
+    /// Disposals deferred while draining are picked up by the same drain.
+    /// Returns the number of disposals that ran.
+    pub async fn drain(&self, cx: &Cx) -> usize {
+        let mut ran = 0;
+        loop {
+            let next = {
+                let mut state = self.inner.state.lock();
+                let next = state.pending.pop();
+                if next.is_none() {
+                    state.sealed = true;
+                }
+                next
+            };
+            let Some(deferred) = next else {
+                break;
+            };
+            let outcome = run_bounded(cx, (deferred.run)(cx.clone()), deferred.budget).await;
+            if !outcome.is_clean() {
+                warn!(
+                    region = ?self.inner.region,
+                    site = %deferred.site,
+                    outcome = %outcome,
+                    "deferred disposal did not complete cleanly"
+                );
+            }
+            self.record(DisposalEvent::Finalized {
+                region: self.inner.region,
+                site: deferred.site,
+                outcome,
+            });
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Region finalizer body: drains under the finalizer task's context.
+    pub(crate) async fn finalize(self) {
+        if let Some(cx) = Cx::current() {
+            self.drain(&cx).await;
+        } else {
+            self.abandon();
+        }
+    }
+
+    fn seal(&self) {
+        self.inner.state.lock().sealed = true;
+    }
+
+    /// Seals the queue without running anything, reporting each pending
+    /// disposal as a leak.
+    fn abandon(&self) {
+        let pending = {
+            let mut state = self.inner.state.lock();
+            state.sealed = true;
+            let pending = std::mem::take(&mut state.pending);
+            for deferred in &pending {
+                state.events.push(DisposalEvent::Leaked(DisposeLeak {
+                    region: self.inner.region,
+                    site: deferred.site,
+                }));
+            }
+            pending
+        };
+        drop(pending);
+    }
+
+    fn defer<T: AsyncDispose + 'static>(&self, value: T, site: DisposalSite, budget: Budget) {
+        let region = self.inner.region;
+        let mut state = self.inner.state.lock();
+        if state.sealed {
+            let leak = DisposeLeak { region, site };
+            state.events.push(DisposalEvent::Leaked(leak.clone()));
+            drop(state);
+            warn!(error = %leak, "disposer dropped after its region closed");
+            drop(value);
+            return;
+        }
+        state.pending.push(Deferred {
+            site,
+            budget,
+            run: Box::new(move |cx: Cx| {
+                Box::pin(async move { value.dispose(&cx).await })
+                    as Pin<Box<dyn Future<Output = DisposeOutcome> + Send>>
+            }),
+        });
+        state.events.push(DisposalEvent::Implicit { region, site });
+        drop(state);
+        warn!(
+            region = ?region,
+            site = %site,
+            "implicit disposal: guard dropped without dispose(); cleanup deferred to region close"
+        );
+    }
+
+    fn record(&self, event: DisposalEvent) {
+        self.inner.state.lock().events.push(event);
+    }
+}
+
+impl fmt::Debug for DisposalQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.state.lock();
+        f.debug_struct("DisposalQueue")
+            .field("region", &self.inner.region)
+            .field("pending", &state.pending.len())
+            .field("sealed", &state.sealed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Region-owned slot holding the region's [`DisposalQueue`].
+///
+/// Every task context of the region shares the slot. The queue is created
+/// on first use; [`close`](Self::close) hands it to the runtime when the
+/// region starts finalizing, after which new queues come back sealed.
+#[derive(Debug, Default)]
+pub(crate) struct RegionDisposalSlot {
+    state: Mutex<SlotState>,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    queue: Option<DisposalQueue>,
+    closed: bool,
+}
+
+impl RegionDisposalSlot {
+    /// Returns a slot that was never attached to a live region.
+    pub(crate) fn closed() -> Self {
+        Self {
+            state: Mutex::new(SlotState {
+                queue: None,
+                closed: true,
+            }),
+        }
+    }
+
+    /// Returns the region's queue, creating it on first use.
+    pub(crate) fn queue(&self, region: RegionId) -> DisposalQueue {
+        let mut state = self.state.lock();
+        let closed = state.closed;
+        state
+            .queue
+            .get_or_insert_with(|| {
+                let queue = DisposalQueue::new(region);
+                if closed {
+                    queue.seal();
+                }
+                queue
+            })
+            .clone()
+    }
+
+    /// Closes the slot, returning the queue to drain if one was created.
+    ///
+    /// Only the first call returns the queue.
+    pub(crate) fn close(&self) -> Option<DisposalQueue> {
+        let mut state = self.state.lock();
+        if std::mem::replace(&mut state.closed, true) {
+            return None;
+        }
+        state.queue.clone()
+    }
+}
+
+/// Guard that guarantees a value is disposed, explicitly or at region close.
+///
+/// Call [`dispose`](Self::dispose) on the happy path. Dropping the guard
+/// while it still holds the value defers disposal to the owning region's
+/// finalize phase and emits [`DisposalEvent::Implicit`].
+///
+/// The value must be owned (`'static`): see the module docs.
+pub struct Disposer<T: AsyncDispose + 'static> {
+    value: Option<T>,
+    queue: DisposalQueue,
+    site: DisposalSite,
+    budget: Budget,
+}
+
+impl<T: AsyncDispose + 'static> Disposer<T> {
+    /// Guards `value`, deferring to `queue` if it is never disposed.
+    ///
+    /// The caller's location is recorded as the guard's [`DisposalSite`].
+    #[track_caller]
+    #[must_use]
+    pub fn new(value: T, queue: &DisposalQueue) -> Self {
+        Self {
+            value: Some(value),
+            queue: queue.clone(),
+            site: DisposalSite {
+                type_name: std::any::type_name::<T>(),
+                location: Location::caller(),
+            },
+            budget: finalizer_budget(),
+        }
+    }
+
+    /// Guards `value`, deferring to the queue of the region `cx` runs in.
+    ///
+    /// The runtime drains that queue when the region finalizes. A context
+    /// not bound to a runtime region has nothing to defer to, so dropping
+    /// the guard records a [`DisposeLeak`].
+    #[track_caller]
+    #[must_use]
+    pub fn in_region(value: T, cx: &Cx) -> Self {
+        Self::new(value, &cx.disposal_queue())
+    }
+
+    /// Sets the budget cleanup runs under, on either path.
+    #[must_use]
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Returns the guarded value, or `None` once disposed.
+    #[must_use]
+    pub fn get(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Returns the guarded value mutably, or `None` once disposed.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.as_mut()
+    }
+
+    /// Returns `true` once the value has been disposed or taken.
+    #[must_use]
+    pub fn is_disposed(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Returns where this guard was constructed.
+    #[must_use]
+    pub fn site(&self) -> DisposalSite {
+        self.site
+    }
+
+    /// Returns the region this guard defers to.
+    #[must_use]
+    pub fn region(&self) -> RegionId {
+        self.queue.region()
+    }
+
+    /// Fails if the guard outlived its region, so dropping it would leak.
+    pub fn check_leak(&self) -> Result<(), DisposeLeak> {
+        if self.value.is_some() && self.queue.is_sealed() {
+            return Err(DisposeLeak {
+                region: self.queue.region(),
+                site: self.site,
+            });
+        }
+        Ok(())
+    }
+
+    /// Disposes the value now, bypassing the implicit path.
+    ///
+    /// Idempotent: later calls return [`DisposeOutcome::AlreadyDisposed`].
+    pub async fn dispose(&mut self, cx: &Cx) -> DisposeOutcome {
+        let Some(value) = self.value.take() else {
+            return DisposeOutcome::AlreadyDisposed;
+        };
+        run_bounded(cx, value.dispose(cx), self.budget).await
+    }
+
+    /// Takes the value out without disposing it.
+    ///
+    /// The caller becomes responsible for cleanup.
+    #[must_use]
+    pub fn into_inner(mut self) -> Option<T> {
+        self.value.take()
+    }
+}
+
+impl<T: AsyncDispose + 'static> Drop for Disposer<T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.queue.defer(value, self.site, self.budget);
+        }
+    }
+}
+
+impl<T: AsyncDispose + 'static> fmt::Debug for Disposer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Disposer")
+            .field("site", &self.site)
+            .field("region", &self.queue.region())
+            .field("disposed", &self.value.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Drives `future` masked against cancellation, within `budget`.
+async fn run_bounded<F>(cx: &Cx, future: F, budget: Budget) -> DisposeOutcome
+where
+    F: Future<Output = DisposeOutcome>,
+{
+    let bounded = Bounded {
+        inner: CatchUnwind { inner: future },
+        cx,
+        polls_left: budget.poll_quota,
+    };
+    if let Some(deadline) = budget.deadline {
+        return crate::time::timeout_at(deadline, bounded)
+            .await
+            .unwrap_or(DisposeOutcome::TimedOut);
+    }
+    bounded.await
+}
+
+#[pin_project::pin_project]
+struct Bounded<'a, F> {
+    #[pin]
+    inner: CatchUnwind<F>,
+    cx: &'a Cx,
+    polls_left: u32,
+}
+
+impl<F: Future<Output = DisposeOutcome>> Future for Bounded<'_, F> {
+    type Output = DisposeOutcome;
+
+    fn poll(self: Pin<&mut Self>, task_cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if *this.polls_left == 0 {
+            return Poll::Ready(DisposeOutcome::TimedOut);
+        }
+        *this.polls_left -= 1;
+        let inner = this.inner;
+        match this.cx.masked(|| inner.poll(task_cx)) {
+            Poll::Ready(Ok(outcome)) => Poll::Ready(outcome),
+            Poll::Ready(Err(payload)) => {
+                Poll::Ready(DisposeOutcome::Panicked(payload_to_string(&payload)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Crate resources
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "sqlite")]
+impl AsyncDispose for crate::database::SqliteConnection {
+    async fn dispose(self, cx: &Cx) -> DisposeOutcome {
+        DisposeOutcome::from_outcome(self.close_async(cx).await)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl AsyncDispose for crate::database::PgConnection {
+    async fn dispose(mut self, _cx: &Cx) -> DisposeOutcome {
+        DisposeOutcome::from_result(self.close().await)
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl AsyncDispose for crate::database::MySqlConnection {
+    async fn dispose(mut self, _cx: &Cx) -> DisposeOutcome {
+        DisposeOutcome::from_result(self.close().await)
+    }
+}
+
+/// Sends `close_notify` and waits for the peer's.
+#[cfg(feature = "tls")]
+impl<IO> AsyncDispose for crate::tls::TlsStream<IO>
+where
+    IO: crate::io::AsyncRead + crate::io::AsyncWrite + Unpin + Send,
+{
+    async fn dispose(mut self, _cx: &Cx) -> DisposeOutcome {
+        let shutdown = std::future::poll_fn(|task_cx| self.poll_shutdown_tls(task_cx)).await;
+        DisposeOutcome::from_result(shutdown)
+    }
+}
+
+/// Syncs written data and metadata to disk.
+#[cfg(not(target_arch = "wasm32"))]
+impl AsyncDispose for crate::fs::File {
+    async fn dispose(self, _cx: &Cx) -> DisposeOutcome {
+        DisposeOutcome::from_result(self.sync_all().await)
+    }
+}
+
+/// Flushes buffered bytes, then shuts the writer down.
+#[cfg(not(target_arch = "wasm32"))]
+impl<W> AsyncDispose for crate::fs::BufWriter<W>
+where
+    W: crate::io::AsyncWrite + Unpin + Send,
+{
+    async fn dispose(mut self, _cx: &Cx) -> DisposeOutcome {
+        use crate::io::AsyncWriteExt;
+
+        if let Err(err) = self.flush().await {
+            return DisposeOutcome::Failed(err.to_string());
+        }
+        DisposeOutcome::from_result(self.shutdown().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Scope;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::types::CancelReason;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Waker;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    enum Behavior {
+        Close,
+        Hang,
+        Panic,
+    }
+
+    struct Probe {
+        behavior: Behavior,
+        disposed: Arc<AtomicUsize>,
+        polls: Arc<AtomicUsize>,
+    }
+
+    impl Probe {
+        fn new(behavior: Behavior) -> (Self, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+            let disposed = Arc::new(AtomicUsize::new(0));
+            let polls = Arc::new(AtomicUsize::new(0));
+            let probe = Self {
+                behavior,
+                disposed: Arc::clone(&disposed),
+                polls: Arc::clone(&polls),
+            };
+            (probe, disposed, polls)
+        }
+    }
+
+    impl AsyncDispose for Probe {
+        async fn dispose(self, cx: &Cx) -> DisposeOutcome {
+            assert!(cx.checkpoint().is_ok(), "disposal runs masked");
+            match self.behavior {
+                Behavior::Close => {
+                    self.disposed.fetch_add(1, Ordering::SeqCst);
+                    DisposeOutcome::Completed
+                }
+                Behavior::Hang => {
+                    let polls = self.polls;
+                    std::future::poll_fn(|task_cx| {
+                        polls.fetch_add(1, Ordering::SeqCst);
+                        task_cx.waker().wake_by_ref();
+                        Poll::<DisposeOutcome>::Pending
+                    })
+                    .await
+                }
+                Behavior::Panic => panic!("disposal boom"),
+            }
+        }
+    }
+
+    fn test_region() -> RegionId {
+        RegionId::new_for_test(1, 0)
+    }
+
+    #[test]
+    fn implicit_disposal_runs_during_region_close() {
+        init_test("implicit_disposal_runs_during_region_close");
+        let mut runtime = LabRuntime::new(LabConfig::new(7));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let scope: Scope<'static> = Scope::new(region, Budget::INFINITE);
+        let queue = scope
+            .disposal_queue(&mut runtime.state)
+            .expect("open region accepts a disposal queue");
+
+        let (probe, disposed, _) = Probe::new(Behavior::Close);
+        let disposer = Disposer::new(probe, &queue);
+        let line = line!() - 1;
+        drop(disposer);
+
+        let pending = queue.pending();
+        crate::assert_with_log!(pending == 1, "deferred to region close", 1, pending);
+        let early = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(early == 0, "nothing runs before close", 0, early);
+
+        let effects = runtime
+            .state
+            .cancel_request(region, &CancelReason::shutdown(), None);
+        let (tasks, wakes) = effects.into_parts();
+        {
+            let mut scheduler = runtime.scheduler.lock();
+            for (task, priority) in tasks {
+                scheduler.schedule_cancel(task, priority);
+            }
+        }
+        wakes.dispatch();
+        runtime.run_until_quiescent();
+
+        let ran = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(ran == 1, "disposed at region close", 1, ran);
+        let events = queue.events();
+        let implicit_line = match events.first() {
+            Some(DisposalEvent::Implicit { region: r, site }) if *r == region => {
+                site.location().line()
+            }
+            _ => 0,
+        };
+        crate::assert_with_log!(
+            implicit_line == line,
+            "implicit event names the construction site",
+            line,
+            implicit_line
+        );
+        let finalized = matches!(
+            events.get(1),
+            Some(DisposalEvent::Finalized { outcome: DisposeOutcome::Completed, .. })
+        );
+        crate::assert_with_log!(finalized, "finalized event", true, finalized);
+        crate::assert_with_log!(queue.is_sealed(), "sealed", true, queue.is_sealed());
+        crate::test_complete!("implicit_disposal_runs_during_region_close");
+    }
+
+    #[test]
+    fn task_disposer_is_drained_by_its_region_without_registration() {
+        init_test("task_disposer_is_drained_by_its_region_without_registration");
+        let mut runtime = LabRuntime::new(LabConfig::new(11));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let (probe, disposed, _) = Probe::new(Behavior::Close);
+        let seen = Arc::new(Mutex::new(None));
+
+        let task_seen = Arc::clone(&seen);
+        let (task, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("task context");
+                let disposer = Disposer::in_region(probe, &cx);
+                *task_seen.lock() = Some(cx.disposal_queue());
+                drop(disposer);
+            })
+            .expect("spawn task");
+        runtime.scheduler.lock().schedule(task, 0);
+        runtime.run_until_quiescent();
+
+        let queue = seen.lock().take().expect("task ran");
+        let pending = queue.pending();
+        crate::assert_with_log!(pending == 1, "deferred to region close", 1, pending);
+
+        let effects = runtime
+            .state
+            .cancel_request(region, &CancelReason::shutdown(), None);
+        let (tasks, wakes) = effects.into_parts();
+        {
+            let mut scheduler = runtime.scheduler.lock();
+            for (task, priority) in tasks {
+                scheduler.schedule_cancel(task, priority);
+            }
+        }
+        wakes.dispatch();
+        runtime.run_until_quiescent();
+
+        let ran = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(ran == 1, "disposed at region close", 1, ran);
+        crate::assert_with_log!(queue.is_sealed(), "sealed", true, queue.is_sealed());
+        crate::test_complete!("task_disposer_is_drained_by_its_region_without_registration");
+    }
+
+    #[test]
+    fn unbound_context_disposer_records_a_leak() {
+        init_test("unbound_context_disposer_records_a_leak");
+        let cx = Cx::for_testing();
+        let (probe, disposed, _) = Probe::new(Behavior::Close);
+        let disposer = Disposer::in_region(probe, &cx);
+        let leaked = disposer.check_leak().is_err();
+        crate::assert_with_log!(leaked, "nothing to defer to", true, leaked);
+        drop(disposer);
+
+        let count = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(count == 0, "cleanup did not run", 0, count);
+        crate::test_complete!("unbound_context_disposer_records_a_leak");
+    }
+
+    #[test]
+    fn explicit_disposal_skips_implicit_path_and_is_idempotent() {
+        init_test("explicit_disposal_skips_implicit_path_and_is_idempotent");
+        let cx = Cx::for_testing();
+        let queue = DisposalQueue::new(test_region());
+        let (probe, disposed, _) = Probe::new(Behavior::Close);
+        let mut disposer = Disposer::new(probe, &queue);
+
+        let first = block_on(disposer.dispose(&cx));
+        crate::assert_with_log!(
+            first == DisposeOutcome::Completed,
+            "first dispose",
+            DisposeOutcome::Completed,
+            first
+        );
+        let second = block_on(disposer.dispose(&cx));
+        crate::assert_with_log!(
+            second == DisposeOutcome::AlreadyDisposed,
+            "second dispose is a no-op",
+            DisposeOutcome::AlreadyDisposed,
+            second
+        );
+        drop(disposer);
+
+        let events = queue.events();
+        crate::assert_with_log!(events.is_empty(), "no implicit event", 0, events.len());
+        let ran = block_on(queue.drain(&cx));
+        crate::assert_with_log!(ran == 0, "nothing deferred", 0, ran);
+        let count = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(count == 1, "cleanup ran once", 1, count);
+        crate::test_complete!("explicit_disposal_skips_implicit_path_and_is_idempotent");
+    }
+
+    #[test]
+    fn disposal_under_cancel_is_budget_bounded() {
+        init_test("disposal_under_cancel_is_budget_bounded");
+        let cx = Cx::for_testing();
+        cx.set_cancel_requested(true);
+        let queue = DisposalQueue::new(test_region());
+        let (probe, _, polls) = Probe::new(Behavior::Hang);
+        let mut disposer =
+            Disposer::new(probe, &queue).with_budget(Budget::new().with_poll_quota(4));
+
+        let outcome = block_on(disposer.dispose(&cx));
+        crate::assert_with_log!(
+            outcome == DisposeOutcome::TimedOut,
+            "budget bounds a hanging disposal",
+            DisposeOutcome::TimedOut,
+            outcome
+        );
+        let polled = polls.load(Ordering::SeqCst);
+        crate::assert_with_log!(polled == 4, "polled within quota", 4, polled);
+        crate::assert_with_log!(
+            disposer.is_disposed(),
+            "value consumed",
+            true,
+            disposer.is_disposed()
+        );
+        crate::test_complete!("disposal_under_cancel_is_budget_bounded");
+    }
+
+    #[test]
+    fn panic_in_dispose_is_contained_and_reported() {
+        init_test("panic_in_dispose_is_contained_and_reported");
+        let cx = Cx::for_testing();
+        let queue = DisposalQueue::new(test_region());
+
+        let (probe, _, _) = Probe::new(Behavior::Panic);
+        let mut explicit = Disposer::new(probe, &queue);
+        let outcome = block_on(explicit.dispose(&cx));
+        crate::assert_with_log!(
+            outcome == DisposeOutcome::Panicked("disposal boom".to_string()),
+            "explicit panic contained",
+            "Panicked(disposal boom)",
+            outcome
+        );
+
+        let (probe, _, _) = Probe::new(Behavior::Panic);
+        drop(Disposer::new(probe, &queue));
+        let ran = block_on(queue.drain(&cx));
+        crate::assert_with_log!(ran == 1, "deferred panic ran", 1, ran);
+        let reported = queue.events().iter().any(|event| {
+            matches!(
+                event,
+                DisposalEvent::Finalized { outcome: DisposeOutcome::Panicked(message), .. }
+                    if message == "disposal boom"
+            )
+        });
+        crate::assert_with_log!(reported, "finalized event reports panic", true, reported);
+        crate::test_complete!("panic_in_dispose_is_contained_and_reported");
+    }
+
+    #[test]
+    fn disposer_escaping_its_region_is_a_typed_leak() {
+        init_test("disposer_escaping_its_region_is_a_typed_leak");
+        let cx = Cx::for_testing();
+        let region = test_region();
+        let queue = DisposalQueue::new(region);
+        let (probe, disposed, _) = Probe::new(Behavior::Close);
+        let disposer = Disposer::new(probe, &queue);
+        let site = disposer.site();
+
+        block_on(queue.drain(&cx));
+        let detached = std::thread::spawn(move || {
+            let check = disposer.check_leak();
+            drop(disposer);
+            check
+        });
+        let check = detached.join().expect("detached thread");
+
+        let expected = DisposeLeak { region, site };
+        crate::assert_with_log!(
+            check == Err(expected.clone()),
+            "escape detected before drop",
+            Err::<(), _>(expected.clone()),
+            check
+        );
+        let leaks = queue.leaks();
+        crate::assert_with_log!(
+            leaks == vec![expected.clone()],
+            "drop records the leak",
+            vec![expected],
+            leaks
+        );
+        let count = disposed.load(Ordering::SeqCst);
+        crate::assert_with_log!(count == 0, "cleanup did not run", 0, count);
+        crate::test_complete!("disposer_escaping_its_region_is_a_typed_leak");
+    }
+}
@@ -0,0 +1,793 @@
+//! Region state handoff between processes.
+//!
+//! A zero-downtime deploy drains a region's long-lived stateful tasks (per
+//! user session actors, say) in the old process and recreates them in the
+//! new one. This is not live migration: futures are not moved. Each task
+//! that opts in through [`Handoff`] serializes its own state while it
+//! drains, and the new process rebuilds it from that state.
+//!
+//! # Old process
+//!
+//! [`Scope::export_on_close`] returns a [`RegionHandoff`] that participating
+//! tasks hold. When the region closes, every task observes cancellation and,
+//! in its drain phase, calls [`RegionHandoff::contribute`] (or
+//! [`contribute_with_mailbox`](RegionHandoff::contribute_with_mailbox) to
+//! also carry the messages still queued for it). Once the tasks are done the
+//! region's finalizer seals the contributions into a [`RegionExport`] and
+//! passes it to the handler, which ships [`RegionExport::to_bytes`] to the
+//! new process.
+//!
+//! ```ignore
+//! let handoff = scope.export_on_close(&mut state, |bundle| ship(bundle.to_bytes()))
+//!     .expect("region open");
+//! // inside each session task, after its receive loop ends:
+//! handoff.contribute_with_mailbox(&user, &session, &mut mailbox);
+//! ```
+//!
+//! # New process
+//!
+//! A [`HandoffRegistry`] maps type tags to constructors, and
+//! [`Scope::import`] spawns one resumed task per bundle entry:
+//!
+//! ```ignore
+//! let mut registry = HandoffRegistry::new();
+//! registry.register::<Session, _, _>(|cx, session, pending| run_session(cx, session, pending));
+//! let report = scope.import(&cx, RegionExport::from_bytes(&bytes)?, &registry);
+//! ```
+//!
+//! # Failure isolation
+//!
+//! A panic while serializing one task's state or messages is caught and
+//! recorded in [`RegionExport::failures`]; the other entries are unaffected.
+//! On import every entry stands alone too: an unknown type tag, a version
+//! other than the registered one ([`HandoffError::VersionMismatch`]) or a
+//! state that fails to restore rejects that entry only.
+//!
+//! Entries are sorted by label when the bundle is sealed, so the bundle for
+//! a given set of states does not depend on the order the tasks drained in.
+
+use crate::bytes::Bytes;
+use crate::channel::mpsc;
+use crate::cx::Cx;
+use crate::cx::scope::{Scope, payload_to_string};
+use crate::runtime::TaskHandle;
+use crate::runtime::state::RuntimeState;
+use crate::types::Policy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Current schema version of [`RegionExport`] bundles.
+pub const REGION_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// State that can leave one process and resume in another.
+pub trait Handoff: Sized + Send + 'static {
+    /// Stable name of the state's type, shared by both processes.
+    const TYPE_TAG: &'static str;
+
+    /// Version of the serialized form. Bump it whenever
+    /// [`serialize_state`](Self::serialize_state) changes incompatibly; an
+    /// import only accepts entries with exactly the registered version.
+    const VERSION: u32;
+
+    /// Serializes the state. Called from the task's drain phase.
+    fn serialize_state(&self) -> Bytes;
+
+    /// Rebuilds the state from [`serialize_state`](Self::serialize_state)
+    /// output of the same version.
+    fn restore_state(bytes: &[u8]) -> Result<Self, String>;
+}
+
+/// A [`Handoff`] state whose queued mailbox messages travel with it.
+pub trait HandoffMailbox: Handoff {
+    /// The mailbox message type.
+    type Message: Send + 'static;
+
+    /// Serializes one queued message.
+    fn serialize_message(message: &Self::Message) -> Bytes;
+}
+
+/// One task's contribution to a [`RegionExport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedTask {
+    /// Label the task contributed under, e.g. the session's user id.
+    pub label: String,
+    /// [`Handoff::TYPE_TAG`] of the state.
+    pub type_tag: String,
+    /// [`Handoff::VERSION`] of the state.
+    pub type_version: u32,
+    /// Serialized state.
+    pub state: Vec<u8>,
+    /// Serialized messages that were queued for the task, oldest first.
+    pub pending: Vec<Vec<u8>>,
+}
+
+/// A contribution that could not be serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportFailure {
+    /// Label the task contributed under.
+    pub label: String,
+    /// [`Handoff::TYPE_TAG`] of the state.
+    pub type_tag: String,
+    /// Panic message of the failed serialization.
+    pub reason: String,
+}
+
+/// The states exported by a closing region.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionExport {
+    /// Schema version ([`REGION_EXPORT_SCHEMA_VERSION`]).
+    pub version: u32,
+    /// Exported tasks, sorted by label and type tag.
+    pub entries: Vec<ExportedTask>,
+    /// Contributions whose serialization panicked, sorted likewise.
+    pub failures: Vec<ExportFailure>,
+}
+
+impl RegionExport {
+    /// Encodes the bundle for shipping to another process.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("region export is always encodable")
+    }
+
+    /// Decodes a bundle written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, HandoffError> {
+        let bundle: Self =
+            rmp_serde::from_slice(bytes).map_err(|err| HandoffError::Decode(err.to_string()))?;
+        if bundle.version == 0 || bundle.version > REGION_EXPORT_SCHEMA_VERSION {
+            return Err(HandoffError::Decode(format!(
+                "unsupported region export version {}",
+                bundle.version
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+/// Errors importing a [`RegionExport`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandoffError {
+    /// The bundle could not be decoded.
+    #[error("invalid region export: {0}")]
+    Decode(String),
+    /// No constructor is registered for the entry's type tag.
+    #[error("no constructor registered for handoff type `{type_tag}`")]
+    UnknownType {
+        /// The entry's type tag.
+        type_tag: String,
+    },
+    /// The entry was serialized by an incompatible version of its type.
+    #[error("handoff type `{type_tag}` version {found} is incompatible (expected {expected})")]
+    VersionMismatch {
+        /// The entry's type tag.
+        type_tag: String,
+        /// Version in the bundle.
+        found: u32,
+        /// Version registered in this process.
+        expected: u32,
+    },
+    /// [`Handoff::restore_state`] rejected the entry's state.
+    #[error("restoring handoff type `{type_tag}` failed: {message}")]
+    Restore {
+        /// The entry's type tag.
+        type_tag: String,
+        /// The error returned by `restore_state`.
+        message: String,
+    },
+    /// The resumed task could not be spawned.
+    #[error("spawning resumed task failed: {0}")]
+    Spawn(String),
+}
+
+#[derive(Debug, Default)]
+struct HandoffState {
+    entries: Vec<ExportedTask>,
+    failures: Vec<ExportFailure>,
+    sealed: bool,
+}
+
+/// Collects the states of a closing region's tasks.
+///
+/// Cloned into every participating task; all clones feed the same bundle.
+#[derive(Debug, Clone, Default)]
+pub struct RegionHandoff {
+    state: Arc<Mutex<HandoffState>>,
+}
+
+impl RegionHandoff {
+    /// Creates an unattached collector. [`Scope::export_on_close`] creates
+    /// one that seals itself when the region finalizes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `state` to the bundle under `label`.
+    ///
+    /// Returns `false` if serialization panicked (the failure is recorded in
+    /// the bundle instead) or the bundle was already sealed.
+    pub fn contribute<T: Handoff>(&self, label: &str, state: &T) -> bool {
+        self.push::<T>(label, || (state.serialize_state(), Vec::new()))
+    }
+
+    /// Adds `state` and the messages still queued in `mailbox`.
+    ///
+    /// The mailbox is closed first, so no message can arrive after the
+    /// snapshot; everything queued is drained into the entry.
+    pub fn contribute_with_mailbox<T: HandoffMailbox>(
+        &self,
+        label: &str,
+        state: &T,
+        mailbox: &mut mpsc::Receiver<T::Message>,
+    ) -> bool {
+        mailbox.close();
+        let mut queued = Vec::new();
+        while let Ok(message) = mailbox.try_recv() {
+            queued.push(message);
+        }
+        self.push::<T>(label, || {
+            let pending = queued.iter().map(|message| T::serialize_message(message).to_vec());
+            (state.serialize_state(), pending.collect())
+        })
+    }
+
+    fn push<T: Handoff>(
+        &self,
+        label: &str,
+        serialize: impl FnOnce() -> (Bytes, Vec<Vec<u8>>),
+    ) -> bool {
+        if self.is_sealed() {
+            return false;
+        }
+        // Serialize outside the lock: a panicking serializer must not
+        // poison the bundle for the other tasks.
+        let result = catch_unwind(AssertUnwindSafe(serialize));
+        let mut collected = self.state.lock();
+        if collected.sealed {
+            return false;
+        }
+        match result {
+            Ok((state, pending)) => {
+                collected.entries.push(ExportedTask {
+                    label: label.to_string(),
+                    type_tag: T::TYPE_TAG.to_string(),
+                    type_version: T::VERSION,
+                    state: state.to_vec(),
+                    pending,
+                });
+                true
+            }
+            Err(payload) => {
+                collected.failures.push(ExportFailure {
+                    label: label.to_string(),
+                    type_tag: T::TYPE_TAG.to_string(),
+                    reason: payload_to_string(&payload),
+                });
+                false
+            }
+        }
+    }
+
+    /// Returns true once the bundle has been sealed.
+    #[must_use]
+    pub fn is_sealed(&self) -> bool {
+        self.state.lock().sealed
+    }
+
+    /// Seals the bundle and returns it; later contributions are refused.
+    ///
+    /// Sealing twice returns an empty bundle the second time.
+    #[must_use]
+    pub fn seal(&self) -> RegionExport {
+        let mut collected = self.state.lock();
+        collected.sealed = true;
+        let mut entries = std::mem::take(&mut collected.entries);
+        let mut failures = std::mem::take(&mut collected.failures);
+        drop(collected);
+        entries.sort_by(|a, b| (&a.label, &a.type_tag).cmp(&(&b.label, &b.type_tag)));
+        failures.sort_by(|a, b| (&a.label, &a.type_tag).cmp(&(&b.label, &b.type_tag)));
+        RegionExport {
+            version: REGION_EXPORT_SCHEMA_VERSION,
+            entries,
+            failures,
+        }
+    }
+}
+
+/// Body of a resumed task, built by a registered constructor.
+type ResumeFn = Box<dyn FnOnce(Cx) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+type BuildFn = Box<dyn Fn(&ExportedTask) -> Result<ResumeFn, HandoffError> + Send + Sync>;
+
+struct Constructor {
+    version: u32,
+    build: BuildFn,
+}
+
+/// Maps type tags to the constructors that resume them.
+#[derive(Default)]
+pub struct HandoffRegistry {
+    constructors: BTreeMap<&'static str, Constructor>,
+}
+
+impl std::fmt::Debug for HandoffRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let versions: BTreeMap<_, _> = self
+            .constructors
+            .iter()
+            .map(|(tag, constructor)| (*tag, constructor.version))
+            .collect();
+        f.debug_struct("HandoffRegistry")
+            .field("constructors", &versions)
+            .finish()
+    }
+}
+
+impl HandoffRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `resume` as the constructor for `T`.
+    ///
+    /// `resume` receives the new task's context, the restored state and the
+    /// serialized messages that were queued for it. Registering the same
+    /// type again replaces the previous constructor.
+    pub fn register<T, F, Fut>(&mut self, resume: F) -> &mut Self
+    where
+        T: Handoff,
+        F: Fn(Cx, T, Vec<Bytes>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let resume = Arc::new(resume);
+        let build: BuildFn = Box::new(move |entry: &ExportedTask| {
+            let state = T::restore_state(&entry.state).map_err(|message| HandoffError::Restore {
+                type_tag: T::TYPE_TAG.to_string(),
+                message,
+            })?;
+            let pending: Vec<Bytes> = entry.pending.iter().cloned().map(Bytes::from).collect();
+            let resume = Arc::clone(&resume);
+            let body: ResumeFn = Box::new(move |cx| Box::pin(resume(cx, state, pending)));
+            Ok(body)
+        });
+        self.constructors.insert(
+            T::TYPE_TAG,
+            Constructor {
+                version: T::VERSION,
+                build,
+            },
+        );
+        self
+    }
+
+    fn build(&self, entry: &ExportedTask) -> Result<ResumeFn, HandoffError> {
+        let constructor = self.constructors.get(entry.type_tag.as_str()).ok_or_else(|| {
+            HandoffError::UnknownType {
+                type_tag: entry.type_tag.clone(),
+            }
+        })?;
+        if constructor.version != entry.type_version {
+            return Err(HandoffError::VersionMismatch {
+                type_tag: entry.type_tag.clone(),
+                found: entry.type_version,
+                expected: constructor.version,
+            });
+        }
+        (constructor.build)(entry)
+    }
+}
+
+/// A task resumed by [`Scope::import`].
+#[derive(Debug)]
+pub struct ResumedTask {
+    /// Label the task was exported under.
+    pub label: String,
+    /// Its type tag.
+    pub type_tag: String,
+    /// Handle of the resumed task.
+    pub handle: TaskHandle<()>,
+}
+
+/// An entry [`Scope::import`] did not resume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedEntry {
+    /// Label the task was exported under.
+    pub label: String,
+    /// Why it was rejected.
+    pub error: HandoffError,
+}
+
+/// Outcome of [`Scope::import`], one line per bundle entry.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Entries resumed as tasks, in bundle order.
+    pub resumed: Vec<ResumedTask>,
+    /// Entries that were rejected, in bundle order.
+    pub rejected: Vec<RejectedEntry>,
+}
+
+impl<P: Policy> Scope<'_, P> {
+    /// Collects the handoff states of this region's tasks when it closes.
+    ///
+    /// Registers a finalizer that seals the returned [`RegionHandoff`] and
+    /// passes the bundle to `handler`. Finalizers run after every task in
+    /// the region has completed, so each task's drain-phase contribution is
+    /// in. Returns `None` if the region no longer accepts finalizers.
+    pub fn export_on_close<H>(&self, state: &mut RuntimeState, handler: H) -> Option<RegionHandoff>
+    where
+        H: FnOnce(RegionExport) + Send + 'static,
+    {
+        let handoff = RegionHandoff::new();
+        let sealer = handoff.clone();
+        self.defer_sync(state, move || handler(sealer.seal()))
+            .then_some(handoff)
+    }
+
+    /// Resumes the tasks of `bundle` in this scope's region.
+    ///
+    /// Each entry is rebuilt through the constructor `registry` holds for
+    /// its type tag and spawned with [`Cx::spawn_in`]. Entries that cannot
+    /// be resumed are reported, not fatal.
+    pub fn import(
+        &self,
+        cx: &Cx,
+        bundle: RegionExport,
+        registry: &HandoffRegistry,
+    ) -> ImportReport {
+        let mut report = ImportReport::default();
+        for entry in bundle.entries {
+            let spawned = registry.build(&entry).and_then(|body| {
+                cx.spawn_in(self, body)
+                    .map_err(|err| HandoffError::Spawn(err.to_string()))
+            });
+            match spawned {
+                Ok(handle) => report.resumed.push(ResumedTask {
+                    label: entry.label,
+                    type_tag: entry.type_tag,
+                    handle,
+                }),
+                Err(error) => report.rejected.push(RejectedEntry {
+                    label: entry.label,
+                    error,
+                }),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::run_async_under_lab;
+    use crate::runtime::yield_now;
+    use crate::types::Budget;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// A per-user session actor: a counter fed by its mailbox.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Session {
+        user: String,
+        counter: u64,
+    }
+
+    impl Handoff for Session {
+        const TYPE_TAG: &'static str = "session";
+        const VERSION: u32 = 1;
+
+        fn serialize_state(&self) -> Bytes {
+            Bytes::from(format!("{}={}", self.user, self.counter).into_bytes())
+        }
+
+        fn restore_state(bytes: &[u8]) -> Result<Self, String> {
+            let text = std::str::from_utf8(bytes).map_err(|err| err.to_string())?;
+            let (user, counter) = text.split_once('=').ok_or("missing `=`")?;
+            let counter = counter.parse().map_err(|_| format!("bad counter {counter:?}"))?;
+            Ok(Self {
+                user: user.to_string(),
+                counter,
+            })
+        }
+    }
+
+    impl HandoffMailbox for Session {
+        type Message = u64;
+
+        fn serialize_message(message: &u64) -> Bytes {
+            Bytes::copy_from_slice(&message.to_le_bytes())
+        }
+    }
+
+    fn decode_message(bytes: &[u8]) -> u64 {
+        u64::from_le_bytes(bytes.try_into().expect("8-byte message"))
+    }
+
+    async fn run_session(
+        cx: Cx,
+        mut session: Session,
+        mut mailbox: mpsc::Receiver<u64>,
+        handled: Arc<AtomicUsize>,
+        handoff: RegionHandoff,
+    ) {
+        while let Ok(amount) = mailbox.recv(&cx).await {
+            session.counter += amount;
+            handled.fetch_add(1, Ordering::SeqCst);
+        }
+        // Drain phase: the region is closing, hand the state over.
+        let label = session.user.clone();
+        handoff.contribute_with_mailbox(&label, &session, &mut mailbox);
+    }
+
+    /// Two sessions handle two messages each, then are cancelled with one
+    /// more message queued; returns the sealed bundle.
+    fn export_scenario(seed: u64) -> RegionExport {
+        let (bundle, _) = run_async_under_lab(seed, |cx| async move {
+            let handoff = RegionHandoff::new();
+            let handled = Arc::new(AtomicUsize::new(0));
+            let mut handles = Vec::new();
+            let mut senders = Vec::new();
+            for (user, counter) in [("bob", 10), ("alice", 0)] {
+                let (tx, rx) = mpsc::channel::<u64>(8);
+                let session = Session {
+                    user: user.to_string(),
+                    counter,
+                };
+                let (handled, handoff) = (Arc::clone(&handled), handoff.clone());
+                let handle = cx
+                    .spawn(move |cx| run_session(cx, session, rx, handled, handoff))
+                    .expect("spawn session");
+                handles.push(handle);
+                senders.push(tx);
+            }
+            for tx in &senders {
+                tx.try_send(1).expect("send");
+                tx.try_send(2).expect("send");
+            }
+            for _ in 0..1000 {
+                if handled.load(Ordering::SeqCst) == 4 {
+                    break;
+                }
+                yield_now().await;
+            }
+            // Close: cancel the sessions, then queue a message they will
+            // not get to handle here.
+            for handle in &handles {
+                handle.abort();
+            }
+            for (offset, tx) in (100..).zip(&senders) {
+                tx.try_send(offset).expect("queue");
+            }
+            for mut handle in handles {
+                let _ = handle.join(&cx).await;
+            }
+            handoff.seal()
+        });
+        bundle
+    }
+
+    fn session_registry(resumed: Arc<Mutex<BTreeMap<String, u64>>>) -> HandoffRegistry {
+        let mut registry = HandoffRegistry::new();
+        registry.register::<Session, _, _>(move |_cx, mut session, pending| {
+            let resumed = Arc::clone(&resumed);
+            async move {
+                for message in pending {
+                    session.counter += decode_message(&message);
+                }
+                resumed.lock().insert(session.user, session.counter);
+            }
+        });
+        registry
+    }
+
+    #[test]
+    fn session_handoff_round_trips_counters_and_queued_messages() {
+        init_test("session_handoff_round_trips_counters_and_queued_messages");
+        let bundle = export_scenario(11);
+        let exported: Vec<(String, Vec<u8>, Vec<u64>)> = bundle
+            .entries
+            .iter()
+            .map(|entry| {
+                let pending = entry.pending.iter().map(|m| decode_message(m)).collect();
+                (entry.label.clone(), entry.state.clone(), pending)
+            })
+            .collect();
+        let expected = vec![
+            ("alice".to_string(), b"alice=3".to_vec(), vec![101]),
+            ("bob".to_string(), b"bob=13".to_vec(), vec![100]),
+        ];
+        crate::assert_with_log!(exported == expected, "exported", expected, exported);
+
+        // Ship the bytes to a "new process" and resume there.
+        let bytes = bundle.to_bytes();
+        let resumed = Arc::new(Mutex::new(BTreeMap::new()));
+        let registry = session_registry(Arc::clone(&resumed));
+        let (labels, _) = run_async_under_lab(12, move |cx| async move {
+            let bundle = RegionExport::from_bytes(&bytes).expect("decode bundle");
+            let report = cx.scope().import(&cx, bundle, &registry);
+            assert!(report.rejected.is_empty(), "rejected: {:?}", report.rejected);
+            let mut labels = Vec::new();
+            for mut resumed in report.resumed {
+                let _ = resumed.handle.join(&cx).await;
+                labels.push(resumed.label);
+            }
+            labels
+        });
+        crate::assert_with_log!(labels == ["alice", "bob"], "resumed", "alice, bob", labels);
+        let counters = resumed.lock().clone();
+        let expected = BTreeMap::from([("alice".to_string(), 104), ("bob".to_string(), 113)]);
+        crate::assert_with_log!(counters == expected, "counters", expected, counters);
+        crate::test_complete!("session_handoff_round_trips_counters_and_queued_messages");
+    }
+
+    #[test]
+    fn version_mismatch_rejects_only_that_entry() {
+        init_test("version_mismatch_rejects_only_that_entry");
+        let entry = |label: &str, type_tag: &str, type_version: u32, state: &[u8]| ExportedTask {
+            label: label.to_string(),
+            type_tag: type_tag.to_string(),
+            type_version,
+            state: state.to_vec(),
+            pending: Vec::new(),
+        };
+        let bundle = RegionExport {
+            version: REGION_EXPORT_SCHEMA_VERSION,
+            entries: vec![
+                entry("alice", "session", 1, b"alice=5"),
+                entry("bob", "session", 2, b"bob=5"),
+                entry("carol", "cart", 1, b"{}"),
+                entry("dave", "session", 1, b"garbage"),
+            ],
+            failures: Vec::new(),
+        };
+        let resumed = Arc::new(Mutex::new(BTreeMap::new()));
+        let registry = session_registry(Arc::clone(&resumed));
+        let (rejected, _) = run_async_under_lab(13, move |cx| async move {
+            let report = cx.scope().import(&cx, bundle, &registry);
+            for mut resumed in report.resumed {
+                let _ = resumed.handle.join(&cx).await;
+            }
+            report.rejected
+        });
+        let expected = vec![
+            RejectedEntry {
+                label: "bob".to_string(),
+                error: HandoffError::VersionMismatch {
+                    type_tag: "session".to_string(),
+                    found: 2,
+                    expected: 1,
+                },
+            },
+            RejectedEntry {
+                label: "carol".to_string(),
+                error: HandoffError::UnknownType {
+                    type_tag: "cart".to_string(),
+                },
+            },
+            RejectedEntry {
+                label: "dave".to_string(),
+                error: HandoffError::Restore {
+                    type_tag: "session".to_string(),
+                    message: "missing `=`".to_string(),
+                },
+            },
+        ];
+        crate::assert_with_log!(rejected == expected, "rejections", expected, rejected);
+        let counters = resumed.lock().clone();
+        let expected = BTreeMap::from([("alice".to_string(), 5)]);
+        crate::assert_with_log!(counters == expected, "alice resumed", expected, counters);
+        crate::test_complete!("version_mismatch_rejects_only_that_entry");
+    }
+
+    #[test]
+    fn serialize_panic_is_isolated() {
+        init_test("serialize_panic_is_isolated");
+        struct Fragile(bool);
+
+        impl Handoff for Fragile {
+            const TYPE_TAG: &'static str = "fragile";
+            const VERSION: u32 = 3;
+
+            fn serialize_state(&self) -> Bytes {
+                assert!(!self.0, "state is not serializable");
+                Bytes::copy_from_slice(b"ok")
+            }
+
+            fn restore_state(_bytes: &[u8]) -> Result<Self, String> {
+                Ok(Self(false))
+            }
+        }
+
+        let handoff = RegionHandoff::new();
+        let accepted = [
+            handoff.contribute("c", &Fragile(false)),
+            handoff.contribute("b", &Fragile(true)),
+            handoff.contribute("a", &Fragile(false)),
+        ];
+        crate::assert_with_log!(
+            accepted == [true, false, true],
+            "accepted",
+            [true, false, true],
+            accepted
+        );
+        let bundle = handoff.seal();
+        let labels: Vec<&str> = bundle.entries.iter().map(|e| e.label.as_str()).collect();
+        crate::assert_with_log!(labels == ["a", "c"], "intact entries", "a, c", labels);
+        let failure = &bundle.failures[0];
+        crate::assert_with_log!(
+            failure.label == "b" && failure.reason.contains("not serializable"),
+            "failure recorded",
+            "b",
+            failure
+        );
+        let late = handoff.contribute("d", &Fragile(false));
+        crate::assert_with_log!(!late, "sealed bundle refuses", false, late);
+
+        let decoded = RegionExport::from_bytes(&bundle.to_bytes()).expect("decode");
+        crate::assert_with_log!(decoded == bundle, "bytes round trip", bundle, decoded);
+        let truncated = RegionExport::from_bytes(&[0x92]);
+        let is_decode = matches!(truncated, Err(HandoffError::Decode(_)));
+        crate::assert_with_log!(is_decode, "truncated bundle", true, is_decode);
+        crate::test_complete!("serialize_panic_is_isolated");
+    }
+
+    #[test]
+    fn bundle_contents_are_deterministic_under_lab() {
+        init_test("bundle_contents_are_deterministic_under_lab");
+        let reference = export_scenario(21).to_bytes();
+        for seed in [21, 22, 23] {
+            let bytes = export_scenario(seed).to_bytes();
+            crate::assert_with_log!(bytes == reference, "same bundle", seed, bytes.len());
+        }
+        crate::test_complete!("bundle_contents_are_deterministic_under_lab");
+    }
+
+    #[test]
+    fn export_on_close_seals_in_region_finalizer() {
+        init_test("export_on_close_seals_in_region_finalizer");
+        let mut state = RuntimeState::new();
+        let region = state.create_root_region(Budget::INFINITE);
+        let scope: Scope<'static> = Scope::new(region, Budget::INFINITE);
+        let shipped = Arc::new(Mutex::new(None));
+        let sink = Arc::clone(&shipped);
+        let handoff = scope
+            .export_on_close(&mut state, move |bundle| *sink.lock() = Some(bundle))
+            .expect("region accepts finalizers");
+        let session = Session {
+            user: "erin".to_string(),
+            counter: 7,
+        };
+        handoff.contribute("erin", &session);
+
+        let region_record = state.regions.get_mut(region.arena_index()).expect("region");
+        region_record.begin_close(None);
+        region_record.begin_finalize();
+        let _ = state.run_sync_finalizers(region);
+
+        let bundle = shipped.lock().take().expect("handler ran");
+        crate::assert_with_log!(bundle.entries.len() == 1, "one entry", 1, bundle.entries.len());
+        let restored = Session::restore_state(&bundle.entries[0].state).expect("restore");
+        crate::assert_with_log!(restored == session, "state", session, restored);
+        crate::assert_with_log!(handoff.is_sealed(), "sealed", true, handoff.is_sealed());
+        crate::test_complete!("export_on_close_seals_in_region_finalizer");
+    }
+}
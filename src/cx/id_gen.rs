@@ -0,0 +1,35 @@
+//! Capability-backed generation of FrankenSuite trace and decision ids.
+//!
+//! [`Cx::next_trace_id`](super::Cx::next_trace_id) and
+//! [`Cx::next_decision_id`](super::Cx::next_decision_id) build UUIDv7-style
+//! ids (48-bit millisecond timestamp, 80 random bits) from the context's own
+//! capabilities instead of ambient wall-clock time and OS randomness:
+//!
+//! - **Lab** (deterministic entropy): the timestamp comes from the timer
+//!   driver's virtual clock and the random bits from the seeded entropy
+//!   source, so the same seed yields the same id sequence.
+//! - **Production**: the timestamp is non-decreasing Unix milliseconds from
+//!   the time provider and the random bits come from OS entropy.
+
+/// `EntropySource::source_id` reported by [`DetEntropy`](crate::util::DetEntropy).
+pub(crate) const DETERMINISTIC_SOURCE_ID: &str = "deterministic";
+
+/// Mask selecting the 80 random bits of a UUIDv7-style id.
+const RANDOM_BITS_MASK: u128 = (1 << 80) - 1;
+
+/// Packs two entropy draws into the 80 random bits of an id.
+pub(crate) fn random_bits(high: u64, low: u64) -> u128 {
+    ((u128::from(high) << 64) | u128::from(low)) & RANDOM_BITS_MASK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_bits_fit_in_eighty_bits() {
+        let bits = random_bits(u64::MAX, u64::MAX);
+        assert_eq!(bits, RANDOM_BITS_MASK);
+        assert_eq!(random_bits(0, 7), 7);
+    }
+}
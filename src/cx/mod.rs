@@ -36,20 +36,36 @@
 //!
 //! - [`Cx`]: The capability context token
 //! - [`Scope`]: API for spawning tasks and creating child regions
+//! - [`CancelPoints`]: Cheap cancellation checks for CPU-bound loops
+//! - [`CxRng`]: Deterministic, task-scoped random streams
+//! - [`Disposer`]: Awaited cleanup for resources dropped without `dispose().await`
+//! - [`handoff`]: Export and import of region task state for process handoff
 
+pub mod attenuation;
+pub mod cancel_point;
 pub mod cap;
+pub mod capability_audit;
 pub mod capacity_ticket;
 pub mod cx;
+pub mod dispose;
+pub mod handoff;
+pub(crate) mod id_gen;
 pub mod macaroon;
 pub mod registry;
+pub mod rng;
 pub mod scope;
 pub mod scoped_cpu;
 pub mod wrappers;
 
+pub use attenuation::{Capability, CapabilityDenied, CapabilityRestriction, CapabilitySet};
+pub use cancel_point::CancelPoints;
 pub use cap::{
     All as AllCaps, CapMask, CapSet, CapSetRuntimeMask, HasIo, HasRandom, HasRemote, HasSpawn,
     HasTime, None as NoCaps, SubsetOf,
 };
+pub use capability_audit::{
+    CAPABILITY_USAGE_LOG_SCHEMA_VERSION, CapabilityAudit, CapabilityAuditMode, CapabilityUse,
+};
 pub use capacity_ticket::{
     CapacityTicket, CapacityTicketId, CapacityTicketReceipt, CapacityTicketReceiptStatus,
     CapacityTicketRefusal, CapacityTicketRequest, CapacityTicketWorkKind, request_capacity_ticket,
@@ -60,6 +76,14 @@ pub use cx::{
     BudgetStats, CapabilityLayerSnapshot, CapabilitySnapshot, CostBudgetStats, Cx,
     DeadlineBudgetStats, PollBudgetStats, SpanGuard,
 };
+pub use dispose::{
+    AsyncDispose, DisposalEvent, DisposalQueue, DisposalSite, DisposeLeak, DisposeOutcome, Disposer,
+};
+pub use handoff::{
+    ExportFailure, ExportedTask, Handoff, HandoffError, HandoffMailbox, HandoffRegistry,
+    ImportReport, REGION_EXPORT_SCHEMA_VERSION, RegionExport, RegionHandoff, RejectedEntry,
+    ResumedTask,
+};
 pub use macaroon::{
     BindError, CaveatPredicate, MacaroonKeyRing, MacaroonToken, VerificationContext,
     VerificationError,
@@ -67,7 +91,8 @@ pub use macaroon::{
 pub use registry::{
     NameLease, NameLeaseError, NameRegistry, RegistryCap, RegistryEvent, RegistryHandle,
 };
-pub use scope::Scope;
+pub use rng::CxRng;
+pub use scope::{Scope, TrySpawnError};
 pub use scoped_cpu::{CpuCx, ScopedCpu, ScopedCpuError};
 pub use wrappers::{
     BackgroundCaps, BackgroundContext, EntropyCaps, GrpcCaps, GrpcContext, PureCaps, WebCaps,
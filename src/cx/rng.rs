@@ -0,0 +1,387 @@
+//! Deterministic, task-scoped random streams.
+//!
+//! [`Cx::rng`](super::Cx::rng) and [`Cx::rng_named`](super::Cx::rng_named)
+//! hand out [`CxRng`] handles for application randomness (shard
+//! assignment, jitter, sampling) that replays with the lab seed instead of
+//! escaping it the way `rand::thread_rng()` does.
+//!
+//! Each task owns a set of streams. A stream's seed is derived from the
+//! task's entropy source, the task's identity, and the stream name, and
+//! does not depend on draws made anywhere else:
+//!
+//! - **Lab**: the task's [`DetEntropy`](crate::util::DetEntropy) fork
+//!   supplies the seed without consuming a draw, so the same lab seed gives
+//!   every task the same sequences on replay.
+//! - **Production**: streams are seeded from OS entropy, unless
+//!   [`RuntimeBuilder::rng_seed`](crate::runtime::RuntimeBuilder::rng_seed)
+//!   pins them for reproducible debugging.
+//!
+//! Named streams are independent: adding a draw to `"retry_jitter"` leaves
+//! the `"shard_assignment"` sequence unchanged, so traces from two versions
+//! of the code stay comparable subsystem by subsystem.
+//!
+//! Streams are for simulation-visible decisions, not secrets; use
+//! [`Cx::random_bytes`](super::Cx::random_bytes) for key material.
+
+use crate::time::TimerDriverHandle;
+use crate::trace::{TraceBufferHandle, TraceEvent};
+use crate::types::TaskId;
+use crate::util::{DetEntropy, DetRng, EntropySource};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+/// Name of the stream returned by [`Cx::rng`](super::Cx::rng).
+pub const DEFAULT_STREAM: &str = "default";
+
+/// The random streams of one task.
+#[derive(Debug, Default)]
+pub(crate) struct RngStreams {
+    root: OnceLock<u64>,
+    streams: Mutex<BTreeMap<Arc<str>, Arc<Mutex<Stream>>>>,
+}
+
+impl RngStreams {
+    /// Returns stream `name`, creating it on first use.
+    pub(crate) fn stream(
+        &self,
+        name: &str,
+        entropy: &dyn EntropySource,
+        task: TaskId,
+    ) -> (Arc<str>, Arc<Mutex<Stream>>) {
+        let root = *self.root.get_or_init(|| {
+            DetEntropy::mix_seed(entropy.stream_seed() ^ DetEntropy::task_seed(task))
+        });
+        let mut streams = self.streams.lock();
+        if let Some((name, stream)) = streams.get_key_value(name) {
+            return (Arc::clone(name), Arc::clone(stream));
+        }
+        let name: Arc<str> = Arc::from(name);
+        let stream = Arc::new(Mutex::new(Stream {
+            rng: DetRng::new(DetEntropy::mix_seed(root ^ name_hash(&name))),
+            draws: 0,
+        }));
+        streams.insert(Arc::clone(&name), Arc::clone(&stream));
+        (name, stream)
+    }
+}
+
+/// Position of one stream.
+pub(crate) struct Stream {
+    rng: DetRng,
+    draws: u64,
+}
+
+impl fmt::Debug for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream")
+            .field("draws", &self.draws)
+            .finish_non_exhaustive()
+    }
+}
+
+/// FNV-1a, so stream seeds do not depend on the standard library's hasher.
+fn name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A handle to one of a task's random streams.
+///
+/// Clones share the stream's position. Obtained from
+/// [`Cx::rng`](super::Cx::rng) or [`Cx::rng_named`](super::Cx::rng_named);
+/// asking for the same stream again continues where the last handle left
+/// off.
+#[derive(Clone)]
+pub struct CxRng {
+    name: Arc<str>,
+    stream: Arc<Mutex<Stream>>,
+    trace: Option<TraceBufferHandle>,
+    timer: Option<TimerDriverHandle>,
+    record: bool,
+}
+
+impl fmt::Debug for CxRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CxRng")
+            .field("name", &self.name)
+            .field("draws", &self.draws())
+            .field("record", &self.record)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CxRng {
+    pub(crate) fn new(
+        (name, stream): (Arc<str>, Arc<Mutex<Stream>>),
+        trace: Option<TraceBufferHandle>,
+        timer: Option<TimerDriverHandle>,
+    ) -> Self {
+        Self {
+            name,
+            stream,
+            trace,
+            timer,
+            record: false,
+        }
+    }
+
+    /// Records every draw from this handle as an `rng_draw` user trace
+    /// event carrying the stream name, the draw's index within the stream,
+    /// and a fingerprint of the value, so two runs' traces can be compared
+    /// draw by draw. Does nothing without a trace buffer.
+    #[must_use]
+    pub const fn record_draws(mut self) -> Self {
+        self.record = true;
+        self
+    }
+
+    /// The stream's name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Draws taken from the stream so far, through any handle.
+    #[must_use]
+    pub fn draws(&self) -> u64 {
+        self.stream.lock().draws
+    }
+
+    /// Returns the next `u64`.
+    #[must_use]
+    pub fn next_u64(&self) -> u64 {
+        let (value, index) = {
+            let mut stream = self.stream.lock();
+            stream.draws += 1;
+            (stream.rng.next_u64(), stream.draws)
+        };
+        if self.record {
+            self.record_draw(index, value);
+        }
+        value
+    }
+
+    /// Returns the next `u32`.
+    #[must_use]
+    pub fn next_u32(&self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a `usize` in `[0, bound)` with rejection sampling.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    #[must_use]
+    pub fn next_usize(&self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be non-zero");
+        let bound = bound as u64;
+        let threshold = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < threshold {
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    /// Returns a random boolean.
+    #[must_use]
+    pub fn next_bool(&self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// Returns an `f64` in `[0, 1)`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Fills `dest` with random bytes.
+    pub fn fill_bytes(&self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Shuffles `slice` in place using Fisher-Yates.
+    pub fn shuffle<T>(&self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.next_usize(i + 1);
+            slice.swap(i, j);
+        }
+    }
+
+    fn record_draw(&self, index: u64, value: u64) {
+        let Some(trace) = &self.trace else {
+            return;
+        };
+        let now = self
+            .timer
+            .as_ref()
+            .map_or_else(crate::time::wall_now, TimerDriverHandle::now);
+        // A fingerprint rather than the value: enough to spot a diverging
+        // draw without handing the stream's output to trace readers.
+        let fingerprint = DetEntropy::mix_seed(value) >> 32;
+        let message = format!(
+            "rng_draw stream={} draw={index} fingerprint={fingerprint:08x}",
+            self.name
+        );
+        trace.record_event(|seq| TraceEvent::user_trace(seq, now, message));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::cx::Cx;
+    use crate::lab::LabRuntime;
+    use crate::trace::TraceData;
+    use crate::types::{Budget, RegionId};
+    use crate::util::{OsEntropy, SeededStreams};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn cx_with(task: u32, entropy: Arc<dyn EntropySource>) -> Cx {
+        Cx::new_with_observability(
+            RegionId::new_for_test(0, 1),
+            TaskId::new_for_test(task, 0),
+            Budget::INFINITE,
+            None,
+            None,
+            Some(entropy),
+        )
+    }
+
+    fn take(rng: &CxRng, count: usize) -> Vec<u64> {
+        (0..count).map(|_| rng.next_u64()).collect()
+    }
+
+    /// Runs two lab tasks that draw from their default and a named stream,
+    /// returning each task's draws in task order, and the lab's recorded
+    /// draw events.
+    fn lab_draws(seed: u64) -> (Vec<Vec<u64>>, Vec<String>) {
+        let draws = Arc::new(Mutex::new(BTreeMap::new()));
+        let mut runtime = LabRuntime::with_seed(seed);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        for label in 0..2u8 {
+            let draws = Arc::clone(&draws);
+            let (task, _handle) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, async move {
+                    let cx = Cx::current().expect("lab cx");
+                    let mut seen = take(&cx.rng(), 3);
+                    seen.extend(take(&cx.rng_named("shard_assignment").record_draws(), 3));
+                    draws.lock().insert(label, seen);
+                })
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task, 0);
+        }
+        runtime.run_until_quiescent();
+        let recorded = runtime
+            .trace()
+            .snapshot()
+            .into_iter()
+            .filter_map(|event| match event.data {
+                TraceData::Message(message) if message.starts_with("rng_draw") => Some(message),
+                _ => None,
+            })
+            .collect();
+        let draws = draws.lock().values().cloned().collect();
+        (draws, recorded)
+    }
+
+    #[test]
+    fn same_seed_replays_identical_draws() {
+        init_test("same_seed_replays_identical_draws");
+        let (first, first_trace) = lab_draws(42);
+        let (replay, replay_trace) = lab_draws(42);
+        crate::assert_with_log!(first == replay, "replayed draws", first, replay);
+        crate::assert_with_log!(
+            first_trace == replay_trace,
+            "replayed draw events",
+            first_trace,
+            replay_trace
+        );
+        // Only the recorded stream shows up: three draws per task.
+        let recorded = first_trace.len();
+        crate::assert_with_log!(recorded == 6, "recorded draws", 6, recorded);
+        let named = first_trace
+            .iter()
+            .all(|event| event.contains("stream=shard_assignment"));
+        crate::assert_with_log!(named, "recorded stream", true, first_trace);
+
+        let (other, _) = lab_draws(43);
+        crate::assert_with_log!(first != other, "seed changes draws", "different", other);
+        crate::test_complete!("same_seed_replays_identical_draws");
+    }
+
+    #[test]
+    fn tasks_draw_independent_sequences() {
+        init_test("tasks_draw_independent_sequences");
+        let (draws, _) = lab_draws(7);
+        crate::assert_with_log!(draws[0] != draws[1], "lab tasks differ", "different", draws);
+
+        // Same entropy seed, different task identity.
+        let a = cx_with(1, Arc::new(DetEntropy::new(5)));
+        let b = cx_with(2, Arc::new(DetEntropy::new(5)));
+        let (a, b) = (take(&a.rng(), 4), take(&b.rng(), 4));
+        crate::assert_with_log!(a != b, "task identity", "different", (a, b));
+        crate::test_complete!("tasks_draw_independent_sequences");
+    }
+
+    #[test]
+    fn named_streams_do_not_perturb_each_other() {
+        init_test("named_streams_do_not_perturb_each_other");
+        let baseline = cx_with(1, Arc::new(DetEntropy::new(11)));
+        let shards = take(&baseline.rng_named("shard_assignment"), 5);
+
+        // The same task with extra draws elsewhere, including raw entropy,
+        // and the stream asked for twice.
+        let busier = cx_with(1, Arc::new(DetEntropy::new(11)));
+        let _ = take(&busier.rng_named("retry_jitter"), 9);
+        let _ = busier.random_u64();
+        let _ = take(&busier.rng(), 2);
+        let stream = busier.rng_named("shard_assignment");
+        let mut again = take(&stream, 2);
+        again.extend(take(&busier.rng_named("shard_assignment"), 3));
+        crate::assert_with_log!(again == shards, "isolated stream", shards, again);
+        let draws = stream.draws();
+        crate::assert_with_log!(draws == 5, "shared position", 5, draws);
+        crate::test_complete!("named_streams_do_not_perturb_each_other");
+    }
+
+    #[test]
+    fn fixed_seed_reproduces_streams_over_os_entropy() {
+        init_test("fixed_seed_reproduces_streams_over_os_entropy");
+        let seeded = |task| cx_with(task, Arc::new(SeededStreams::new(Arc::new(OsEntropy), 9)));
+        let first = take(&seeded(3).rng(), 4);
+        let again = take(&seeded(3).rng(), 4);
+        crate::assert_with_log!(first == again, "fixed seed", first, again);
+        let other_task = take(&seeded(4).rng(), 4);
+        crate::assert_with_log!(first != other_task, "per task", "different", other_task);
+
+        // Without a fixed seed each context draws its own OS seed.
+        let unseeded = take(&cx_with(3, Arc::new(OsEntropy)).rng(), 4);
+        crate::assert_with_log!(first != unseeded, "os seeded", "different", unseeded);
+        crate::test_complete!("fixed_seed_reproduces_streams_over_os_entropy");
+    }
+}
@@ -98,6 +98,7 @@
 
 use crate::channel::oneshot;
 use crate::combinator::{Either, Select};
+use crate::cx::dispose::DisposalQueue;
 use crate::cx::{Cx, cap};
 use crate::record::AdmissionError;
 use crate::record::task::TaskState;
@@ -137,12 +138,13 @@ pub struct Scope<'r, P: Policy = crate::types::policy::FailFast> {
     pub(crate) _policy: PhantomData<&'r P>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct ChildRegionAdmission {
     budget: Budget,
     capability_budget: CapabilityBudget,
     requirements: CapabilityBudgetRequirements,
     priority: RegionPriority,
+    label: Option<Arc<str>>,
 }
 
 #[pin_project::pin_project]
@@ -156,9 +158,9 @@ impl<F: Future> Future for CatchUnwind<F> {
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut this = self.project();
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let result = crate::runtime::panic_strategy::catch_unwind(|| {
             this.inner.as_mut().poll(cx)
-        }));
+        });
         match result {
             Ok(Poll::Pending) => Poll::Pending,
             Ok(Poll::Ready(v)) => Poll::Ready(Ok(v)),
@@ -430,6 +432,7 @@ impl<P: Policy> Scope<'_, P> {
 
         // Create the TaskHandle
         let handle = TaskHandle::new(task_id, rx, Arc::downgrade(&child_cx.inner));
+        let completion = handle.completion_guard();
 
         // Set the shared inner state in the TaskRecord
         // This links the user-facing Cx to the runtime's TaskRecord
@@ -465,6 +468,7 @@ impl<P: Policy> Scope<'_, P> {
         // construction and the produced future, so a factory panic becomes the
         // task's terminal outcome instead of orphaning a Created record.
         let wrapped = async move {
+            completion.start();
             spawn_effects.dispatch();
             let result_result = CatchUnwind {
                 inner: async move { f(child_cx).await },
@@ -472,14 +476,15 @@ impl<P: Policy> Scope<'_, P> {
             .await;
             match result_result {
                 Ok(result) => {
-                    let _ = tx.send_blocking(Ok(result));
+                    let _ = tx.send_blocking(completion.finish(Ok(result)));
                     crate::types::Outcome::Ok(())
                 }
                 Err(payload) => {
                     let msg = payload_to_string(&payload);
                     std::mem::forget(payload);
                     let panic_payload = PanicPayload::new(msg);
-                    let _ = tx.send_blocking(Err(JoinError::Panicked(panic_payload.clone())));
+                    let result = Err(JoinError::Panicked(panic_payload.clone()));
+                    let _ = tx.send_blocking(completion.finish(result));
                     crate::types::Outcome::Panicked(panic_payload)
                 }
             }
@@ -538,6 +543,55 @@ impl<P: Policy> Scope<'_, P> {
         Ok(handle)
     }
 
+    /// Spawns like [`Scope::spawn_registered`], but rejects a full scope
+    /// without consuming the factory.
+    ///
+    /// Capacity is checked before anything is built: the runtime live-task
+    /// limit, each ancestor subtree limit, and this region's own task limit.
+    /// On rejection the factory comes back inside
+    /// [`TrySpawnError::AtCapacity`] next to the typed [`SpawnError`], so the
+    /// caller can wait (for example on
+    /// [`SpawnCapacityGate::wait`](crate::runtime::SpawnCapacityGate::wait))
+    /// and retry the same work.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrySpawnError::AtCapacity`] when a live-task limit is full,
+    /// and [`TrySpawnError::Failed`] for every other spawn failure.
+    pub fn try_spawn<F, Fut, Caps>(
+        &self,
+        state: &mut RuntimeState,
+        cx: &Cx<Caps>,
+        f: F,
+    ) -> Result<TaskHandle<Fut::Output>, TrySpawnError<F>>
+    where
+        Caps: cap::HasSpawn + Send + Sync + 'static,
+        F: FnOnce(Cx<Caps>) -> Fut + Send + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        if let Some(error) = self.capacity_rejection(state) {
+            return Err(TrySpawnError::AtCapacity { error, factory: f });
+        }
+        self.spawn_registered(state, cx, f)
+            .map_err(TrySpawnError::Failed)
+    }
+
+    /// Returns the capacity error a spawn into this scope would hit now.
+    fn capacity_rejection(&self, state: &RuntimeState) -> Option<SpawnError> {
+        if let Err(error) = state.check_spawn_capacity(self.region) {
+            return Some(error);
+        }
+        let region = state.region(self.region)?;
+        let limit = region.max_tasks()?;
+        let live = region.task_count();
+        (live >= limit).then_some(SpawnError::RegionAtCapacity {
+            region: self.region,
+            limit,
+            live,
+        })
+    }
+
     // =========================================================================
     // Child Regions
     // =========================================================================
@@ -635,6 +689,7 @@ impl<P: Policy> Scope<'_, P> {
                 capability_budget: CapabilityBudget::UNSPECIFIED,
                 requirements: CapabilityBudgetRequirements::NONE,
                 priority,
+                label: None,
             },
             _policy,
             f,
@@ -670,6 +725,40 @@ impl<P: Policy> Scope<'_, P> {
                 capability_budget,
                 requirements,
                 priority: RegionPriority::Normal,
+                label: None,
+            },
+            _policy,
+            f,
+        )
+        .await
+    }
+
+    /// Creates a child region carrying a class label.
+    ///
+    /// The label is reported with the child's region lifecycle events; see
+    /// [`RuntimeState::subscribe_region_events`].
+    pub async fn region_with_label<P2, F, Fut, T, Caps>(
+        &self,
+        state: &mut RuntimeState,
+        _cx: &Cx<Caps>,
+        label: impl Into<Arc<str>>,
+        _policy: P2,
+        f: F,
+    ) -> Result<Outcome<T, P2::Error>, RegionCreateError>
+    where
+        P2: Policy,
+        F: FnOnce(Scope<'_, P2>, &mut RuntimeState) -> Fut,
+        Fut: Future<Output = Outcome<T, P2::Error>>,
+    {
+        self.region_with_child_admission(
+            state,
+            _cx,
+            ChildRegionAdmission {
+                budget: self.budget,
+                capability_budget: CapabilityBudget::UNSPECIFIED,
+                requirements: CapabilityBudgetRequirements::NONE,
+                priority: RegionPriority::Normal,
+                label: Some(label.into()),
             },
             _policy,
             f,
@@ -690,12 +779,13 @@ impl<P: Policy> Scope<'_, P> {
         F: FnOnce(Scope<'_, P2>, &mut RuntimeState) -> Fut,
         Fut: Future<Output = Outcome<T, P2::Error>>,
     {
-        let child_region = state.create_child_region_with_capability_budget_and_priority(
+        let child_region = state.create_child_region_with_label(
             self.region,
             admission.budget,
             admission.capability_budget,
             admission.requirements,
             admission.priority,
+            admission.label,
         )?;
         let child_budget = state
             .region(child_region)
@@ -1315,6 +1405,8 @@ impl<P: Policy> Scope<'_, P> {
             Some(child_entropy),
         )
         .with_logical_clock(logical_clock)
+        .with_deadline_clamp_slack(parent_cx.deadline_clamp_slack())
+        .with_capability_audit(parent_cx.capability_audit().cloned())
         .with_registry_handle(registry)
         .with_remote_cap_handle(remote_cap)
         .with_blocking_pool_handle(blocking_pool)
@@ -1340,7 +1432,16 @@ impl<P: Policy> Scope<'_, P> {
                 state
                     .region(self.region)
                     .map(crate::record::RegionRecord::pending_spawn_handle),
+            )
+            .with_disposal_slot(
+                state
+                    .region(self.region)
+                    .map(crate::record::RegionRecord::disposal_slot),
             );
+        child_cx
+            .capability_restriction
+            .clone_from(&parent_cx.capability_restriction);
+        child_cx.record_capability_restriction();
         let child_cx_full = child_cx.retype::<cap::All>();
 
         (child_cx, child_cx_full)
@@ -1357,6 +1458,8 @@ impl<P: Policy> Scope<'_, P> {
             .timer_driver()
             .map_or(state.now, crate::time::TimerDriverHandle::now);
 
+        state.check_spawn_capacity(self.region)?;
+
         let region = self.region;
         let budget = self.budget;
         let idx = state.insert_pooled_task_with(|idx, record| {
@@ -1401,6 +1504,7 @@ impl<P: Policy> Scope<'_, P> {
             state.recycle_task(task_id);
             return Err(SpawnError::RegionNotFound(self.region));
         }
+        state.observe_spawn_capacity();
 
         state.notify_runtime_epoch_advance(crate::runtime::epoch_tracker::ModuleId::TaskTable);
         Ok(task_id)
@@ -1462,6 +1566,24 @@ impl<P: Policy> Scope<'_, P> {
     {
         state.register_async_finalizer(self.region, future)
     }
+
+    /// Returns the [`DisposalQueue`] drained during this region's finalize
+    /// phase.
+    ///
+    /// This is the same queue task contexts of the region reach through
+    /// [`Cx::disposal_queue`](crate::cx::Cx::disposal_queue).
+    /// [`Disposer`](crate::cx::Disposer)s bound to it that are dropped without
+    /// explicit disposal have their cleanup awaited when the region closes.
+    ///
+    /// # Returns
+    /// `None` if the region is closing or gone.
+    #[must_use]
+    pub fn disposal_queue(&self, state: &RuntimeState) -> Option<DisposalQueue> {
+        state
+            .region(self.region)
+            .filter(|region| !region.state().is_closing() && !region.state().is_terminal())
+            .map(crate::record::RegionRecord::disposal_queue)
+    }
 }
 
 impl<P: Policy> std::fmt::Debug for Scope<'_, P> {
@@ -1474,6 +1596,53 @@ impl<P: Policy> std::fmt::Debug for Scope<'_, P> {
     }
 }
 
+/// Error returned by [`Scope::try_spawn`].
+pub enum TrySpawnError<F> {
+    /// A live-task limit is full; the factory was not run.
+    AtCapacity {
+        /// The typed capacity rejection (`AtCapacity` or `RegionAtCapacity`).
+        error: SpawnError,
+        /// The unused task factory, returned for a retry.
+        factory: F,
+    },
+    /// The spawn failed for a reason other than a full limit checked up front.
+    Failed(SpawnError),
+}
+
+impl<F> TrySpawnError<F> {
+    /// Returns the underlying spawn error, dropping any returned factory.
+    #[must_use]
+    pub fn into_spawn_error(self) -> SpawnError {
+        match self {
+            Self::AtCapacity { error, .. } | Self::Failed(error) => error,
+        }
+    }
+}
+
+impl<F> std::fmt::Debug for TrySpawnError<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AtCapacity { error, .. } => f
+                .debug_struct("AtCapacity")
+                .field("error", error)
+                .finish_non_exhaustive(),
+            Self::Failed(error) => f.debug_tuple("Failed").field(error).finish(),
+        }
+    }
+}
+
+impl<F> std::fmt::Display for TrySpawnError<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AtCapacity { error, .. } | Self::Failed(error) => {
+                std::fmt::Display::fmt(error, f)
+            }
+        }
+    }
+}
+
+impl<F> std::error::Error for TrySpawnError<F> {}
+
 #[cfg(test)]
 mod tests {
     #![allow(
@@ -1609,6 +1778,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn spawned_tasks_share_scope_deadline_and_clamp_slack() {
+        use std::task::Context;
+        use std::time::Duration;
+
+        let mut state = RuntimeState::new();
+        let clock = Arc::new(crate::time::VirtualClock::new());
+        state.set_timer_driver(crate::time::TimerDriverHandle::with_virtual_clock(clock));
+
+        let cx = test_cx().with_deadline_clamp_slack(Duration::from_millis(250));
+        let budget = Budget::INFINITE.with_deadline(Time::from_secs(5));
+        let region = state.create_root_region(budget);
+        let scope = test_scope(region, budget);
+
+        let waker = std::task::Waker::noop().clone();
+        let mut poll_cx = Context::from_waker(&waker);
+        for _ in 0..2 {
+            let (mut handle, mut stored) = scope
+                .create_stored_task(&mut state, &cx, |cx| async move {
+                    let _guard = Cx::set_current(Some(cx.clone()));
+                    let clamped =
+                        crate::time::timeout(Time::ZERO, Duration::from_secs(30), async {});
+                    (
+                        cx.deadline(),
+                        cx.deadline_clamp_slack(),
+                        clamped.deadline(),
+                        clamped.fired_by(),
+                    )
+                })
+                .expect("spawn should succeed");
+            assert!(stored.poll(&mut poll_cx).is_ready());
+
+            let mut join_fut = std::pin::pin!(handle.join(&cx));
+            match join_fut.as_mut().poll(&mut poll_cx) {
+                Poll::Ready(Ok((deadline, slack, clamped, fired_by))) => {
+                    assert_eq!(deadline, Some(Time::from_secs(5)));
+                    assert_eq!(slack, Duration::from_millis(250));
+                    assert_eq!(clamped, Time::from_secs(5));
+                    assert_eq!(fired_by, crate::time::FiredBy::Ambient);
+                }
+                other => unreachable!("Expected Ready(Ok(_)), got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn create_task_record_uses_runtime_timer_driver_time() {
         let mut state = RuntimeState::new();
@@ -1654,6 +1868,46 @@ mod tests {
         assert!(stored.is_some(), "spawn_registered should store the task");
     }
 
+    #[test]
+    fn try_spawn_returns_factory_when_region_is_full() {
+        let mut state = RuntimeState::new();
+        let cx = test_cx();
+        let region = state.create_root_region(Budget::INFINITE);
+        let scope = test_scope(region, Budget::INFINITE);
+        let limits = RegionLimits {
+            max_tasks: Some(1),
+            ..RegionLimits::unlimited()
+        };
+        assert!(state.set_region_limits(region, limits));
+
+        let first = scope.try_spawn(&mut state, &cx, |_| async { 1_u8 });
+        assert!(first.is_ok(), "first spawn fits the limit");
+
+        let rejected = scope.try_spawn(&mut state, &cx, |_| async { 2_u8 });
+        let Err(TrySpawnError::AtCapacity { error, factory }) = rejected else {
+            panic!("second spawn must be rejected for capacity");
+        };
+        assert_eq!(
+            error,
+            SpawnError::RegionAtCapacity {
+                region,
+                limit: 1,
+                live: 1,
+            }
+        );
+        assert_eq!(
+            state.region(region).expect("region").task_count(),
+            1,
+            "a rejected spawn creates no task record"
+        );
+
+        assert!(state.set_region_limits(region, RegionLimits::unlimited()));
+        let retried = scope
+            .try_spawn(&mut state, &cx, factory)
+            .expect("returned factory spawns once capacity frees");
+        assert!(state.get_stored_future(retried.task_id()).is_some());
+    }
+
     #[test]
     fn spawn_registered_task_can_be_polled() {
         use std::task::Context;
@@ -0,0 +1,1991 @@
+//! Bulk ingestion with two-phase chunk commit.
+//!
+//! [`BulkLoader`] streams rows into a database in fixed-size chunks. Rows come
+//! either from an async [`Stream`] of typed rows ([`BulkLoader::load_rows`])
+//! or from raw CSV / NDJSON bytes that are decoded and validated on the fly
+//! ([`BulkLoader::load_raw`]). Each chunk is handed to a [`BulkTarget`], the
+//! backend-specific half of the protocol:
+//!
+//! - [`PgCopyTarget`]: PostgreSQL `COPY ... FROM STDIN` with a streaming
+//!   text-format encoder (requires `postgres` feature)
+//! - [`SqliteBulkTarget`]: batched multi-row `INSERT`s inside one transaction
+//!   per chunk (requires `sqlite` feature)
+//! - [`MySqlBulkTarget`]: prepared multi-row `INSERT`s inside one transaction
+//!   per chunk (requires `mysql` feature)
+//!
+//! # Two-phase chunks
+//!
+//! A chunk is *reserved* when it is handed to the target and rows start
+//! flowing to the server; it is *committed* only once the server confirms it
+//! (`CommandComplete` for COPY, a successful `COMMIT` for the transactional
+//! targets). Every chunk therefore ends in exactly one of three states:
+//!
+//! - [`ChunkState::Committed`]: durable on the server
+//! - [`ChunkState::RolledBack`]: reserved but aborted (`CopyFail`,
+//!   `ROLLBACK`, or a dropped connection that the server discards)
+//! - [`ChunkState::Unsent`]: buffered by the loader but never reserved
+//!
+//! Cancellation, target errors, and the malformed-row threshold all stop the
+//! load with a [`BulkReport`] whose [`durable_ranges`](BulkReport::durable_ranges)
+//! are exactly the rows a retry must skip. There is no "unknown" state: the
+//! terminal `CopyDone`/`COMMIT` exchange runs inside a commit section, so a
+//! cancellation that lands mid-confirmation is observed only after the
+//! server has answered.
+//!
+//! # Malformed rows
+//!
+//! Rows that fail validation (wrong column count, CSV quoting errors,
+//! invalid UTF-8, non-object NDJSON lines, nested JSON values, or a typed
+//! row whose conversion failed) are routed to a [`MalformedRowSink`] with
+//! their 1-based row number instead of aborting the load. Set
+//! [`BulkLoader::max_malformed_rows`] to stop once too many rows have been
+//! rejected.
+//!
+//! # Backpressure
+//!
+//! The loader never reads ahead of the target: the source is not polled
+//! again until the current chunk has been confirmed or rolled back, so at
+//! most one chunk of rows is buffered regardless of how slowly the server
+//! reads.
+//!
+//! # MySQL `LOAD DATA LOCAL INFILE`
+//!
+//! The MySQL client never advertises `CLIENT_LOCAL_FILES` and rejects
+//! server-initiated local-infile requests (see the LOAD DATA LOCAL INFILE
+//! security audit in [`mysql`](super::mysql)), so there is no local-infile
+//! path to enable. [`MySqlBulkTarget`] uses prepared multi-row `INSERT`s,
+//! which keeps the same chunk semantics without widening the client's file
+//! access surface.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::database::bulk::{BulkFormat, BulkLoader, MalformedRow, PgCopyTarget};
+//!
+//! let mut target = PgCopyTarget::new(&mut conn, "events");
+//! let mut rejected: Vec<MalformedRow> = Vec::new();
+//! let report = BulkLoader::new(["id", "kind", "payload"])
+//!     .chunk_rows(50_000)
+//!     .max_malformed_rows(Some(100))
+//!     .load_raw(&cx, &mut target, BulkFormat::csv(), csv_bytes, &mut rejected)
+//!     .await;
+//! for range in report.durable_ranges() {
+//!     println!("rows {}..{} are durable", range.start, range.end);
+//! }
+//! ```
+
+use crate::cx::Cx;
+use crate::stream::{Stream, StreamExt};
+use crate::types::{CancelReason, Outcome, PanicPayload};
+use std::fmt;
+use std::future::Future;
+
+#[cfg(feature = "mysql")]
+use super::mysql::{MySqlConnection, MySqlError, MySqlTransaction, ToSql as MySqlToSql};
+#[cfg(feature = "postgres")]
+use super::postgres::{PgConnection, PgError};
+#[cfg(feature = "sqlite")]
+use super::sqlite::{SqliteConnection, SqliteError, SqliteTransaction, SqliteValue};
+
+/// Default number of rows per chunk.
+pub const DEFAULT_CHUNK_ROWS: usize = 10_000;
+
+/// Longest raw-record excerpt kept on a [`MalformedRow`].
+const MAX_RAW_EXCERPT: usize = 1024;
+
+/// Polls the terminal `COMMIT`/`ROLLBACK` of a chunk may run masked.
+#[cfg(any(feature = "sqlite", feature = "mysql"))]
+const CHUNK_TERMINAL_MASKED_POLLS: u32 = 64;
+
+fn cancelled_reason(cx: &Cx) -> CancelReason {
+    cx.cancel_reason().unwrap_or_default()
+}
+
+// ─── Values and rows ─────────────────────────────────────────────────────────
+
+/// One field of a bulk-loaded row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkValue {
+    /// SQL NULL.
+    Null,
+    /// Boolean value.
+    Bool(bool),
+    /// 64-bit integer.
+    Int(i64),
+    /// Double-precision float.
+    Float(f64),
+    /// Text value.
+    Text(String),
+    /// Binary value.
+    Bytes(Vec<u8>),
+}
+
+impl BulkValue {
+    /// Approximate wire size of this value, used for progress accounting
+    /// by targets that do not frame their own payload.
+    #[must_use]
+    pub fn approx_len(&self) -> usize {
+        match self {
+            Self::Null => 0,
+            Self::Bool(_) => 1,
+            Self::Int(_) | Self::Float(_) => 8,
+            Self::Text(text) => text.len(),
+            Self::Bytes(bytes) => bytes.len(),
+        }
+    }
+}
+
+impl From<bool> for BulkValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for BulkValue {
+    fn from(value: i32) -> Self {
+        Self::Int(i64::from(value))
+    }
+}
+
+impl From<i64> for BulkValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for BulkValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for BulkValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for BulkValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<u8>> for BulkValue {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for BulkValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Conversion from a typed row into loader fields.
+///
+/// Fields must be in the loader's column order. Returning `Err` routes the
+/// row to the [`MalformedRowSink`] with the given reason instead of failing
+/// the load.
+pub trait IntoBulkRow {
+    /// Converts this row into one value per loader column.
+    ///
+    /// # Errors
+    ///
+    /// Returns a human-readable reason when the row cannot be loaded.
+    fn into_bulk_row(self) -> Result<Vec<BulkValue>, String>;
+}
+
+impl IntoBulkRow for Vec<BulkValue> {
+    fn into_bulk_row(self) -> Result<Vec<BulkValue>, String> {
+        Ok(self)
+    }
+}
+
+impl<R: IntoBulkRow, E: fmt::Display> IntoBulkRow for Result<R, E> {
+    fn into_bulk_row(self) -> Result<Vec<BulkValue>, String> {
+        self.map_err(|err| err.to_string())?.into_bulk_row()
+    }
+}
+
+/// Half-open range of 1-based source row numbers (`start..end`).
+///
+/// Row numbers count data records in the source: typed-stream items, CSV
+/// records after the header, or non-blank NDJSON lines. A range may contain
+/// rows that were routed to the [`MalformedRowSink`]; those are never
+/// loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RowRange {
+    /// First row number in the range.
+    pub start: u64,
+    /// One past the last row number in the range.
+    pub end: u64,
+}
+
+impl RowRange {
+    /// Number of row numbers covered by the range.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns `true` when the range covers no rows.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Returns `true` when `row` falls inside the range.
+    #[must_use]
+    pub const fn contains(&self, row: u64) -> bool {
+        self.start <= row && row < self.end
+    }
+}
+
+impl fmt::Display for RowRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A chunk of rows handed to a [`BulkTarget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkChunk {
+    index: usize,
+    range: RowRange,
+    rows: Vec<Vec<BulkValue>>,
+}
+
+impl BulkChunk {
+    /// Zero-based position of this chunk in the load.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Source row numbers covered by this chunk.
+    #[must_use]
+    pub const fn range(&self) -> RowRange {
+        self.range
+    }
+
+    /// Rows in this chunk, one value per loader column.
+    #[must_use]
+    pub fn rows(&self) -> &[Vec<BulkValue>] {
+        &self.rows
+    }
+
+    /// Number of rows in this chunk.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` when the chunk holds no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+// ─── Malformed rows ──────────────────────────────────────────────────────────
+
+/// A source row that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedRow {
+    /// 1-based source row number.
+    pub row: u64,
+    /// Why the row was rejected.
+    pub reason: String,
+    /// Raw record bytes for CSV/NDJSON input, truncated to 1 KiB.
+    pub raw: Option<Vec<u8>>,
+}
+
+impl MalformedRow {
+    fn new(row: u64, reason: impl Into<String>, raw: Option<&[u8]>) -> Self {
+        Self {
+            row,
+            reason: reason.into(),
+            raw: raw.map(|raw| raw[..raw.len().min(MAX_RAW_EXCERPT)].to_vec()),
+        }
+    }
+}
+
+impl fmt::Display for MalformedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.reason)
+    }
+}
+
+/// Destination for rows rejected during validation.
+pub trait MalformedRowSink {
+    /// Receives one rejected row.
+    fn route(&mut self, row: MalformedRow);
+}
+
+impl MalformedRowSink for Vec<MalformedRow> {
+    fn route(&mut self, row: MalformedRow) {
+        self.push(row);
+    }
+}
+
+// ─── Targets ─────────────────────────────────────────────────────────────────
+
+/// Per-chunk send accounting filled in by a [`BulkTarget`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkMeter {
+    rows_sent: u64,
+    bytes_sent: u64,
+}
+
+impl ChunkMeter {
+    /// Records rows and payload bytes written to the server.
+    pub const fn record(&mut self, rows: usize, bytes: usize) {
+        self.rows_sent = self.rows_sent.saturating_add(rows as u64);
+        self.bytes_sent = self.bytes_sent.saturating_add(bytes as u64);
+    }
+
+    /// Rows written so far in this chunk.
+    #[must_use]
+    pub const fn rows_sent(&self) -> u64 {
+        self.rows_sent
+    }
+
+    /// Payload bytes written so far in this chunk.
+    #[must_use]
+    pub const fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+}
+
+/// Backend half of a bulk load: makes one chunk durable or rolls it back.
+///
+/// `load_chunk` must resolve to [`Outcome::Ok`] only after the server has
+/// confirmed the chunk, returning the server-reported row count when the
+/// protocol exposes one. Every other outcome means the chunk is not durable:
+/// the target must have aborted it (`CopyFail`, `ROLLBACK`) or left the
+/// connection in a state the server discards (a closed socket mid-COPY).
+pub trait BulkTarget: Send {
+    /// Backend error type.
+    type Error: fmt::Display + Send;
+
+    /// Sends `chunk` and waits for the server to confirm it.
+    fn load_chunk(
+        &mut self,
+        cx: &Cx,
+        columns: &[String],
+        chunk: &BulkChunk,
+        meter: &mut ChunkMeter,
+    ) -> impl Future<Output = Outcome<Option<u64>, Self::Error>> + Send;
+}
+
+/// Quotes a table or column name, rejecting names that cannot be quoted.
+///
+/// Dotted names (`schema.table`) quote each part separately.
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql", test))]
+fn quote_identifier(name: &str, quote: char) -> Result<String, String> {
+    if name.is_empty() || name.contains('\0') {
+        return Err(format!("invalid bulk-load identifier {name:?}"));
+    }
+    let mut quoted = String::with_capacity(name.len() + 2);
+    for (i, part) in name.split('.').enumerate() {
+        if part.is_empty() {
+            return Err(format!("invalid bulk-load identifier {name:?}"));
+        }
+        if i > 0 {
+            quoted.push('.');
+        }
+        quoted.push(quote);
+        for ch in part.chars() {
+            if ch == quote {
+                quoted.push(quote);
+            }
+            quoted.push(ch);
+        }
+        quoted.push(quote);
+    }
+    Ok(quoted)
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite", feature = "mysql", test))]
+fn quoted_column_list(columns: &[String], quote: char) -> Result<String, String> {
+    let mut list = String::new();
+    for (i, column) in columns.iter().enumerate() {
+        if column.contains('.') {
+            return Err(format!("invalid bulk-load column name {column:?}"));
+        }
+        if i > 0 {
+            list.push_str(", ");
+        }
+        list.push_str(&quote_identifier(column, quote)?);
+    }
+    Ok(list)
+}
+
+/// Builds the validated `INSERT INTO table (cols) VALUES ` prefix.
+#[cfg(any(feature = "sqlite", feature = "mysql", test))]
+fn insert_prefix(table: &str, columns: &[String], quote: char) -> Result<String, String> {
+    let table = quote_identifier(table, quote)?;
+    let column_list = quoted_column_list(columns, quote)?;
+    Ok(format!("INSERT INTO {table} ({column_list}) VALUES "))
+}
+
+/// Appends `rows` placeholder tuples of `width` parameters to `prefix`.
+#[cfg(any(feature = "sqlite", feature = "mysql", test))]
+fn multi_row_insert_sql(prefix: &str, width: usize, rows: usize) -> String {
+    let tuple = format!("({})", vec!["?"; width].join(", "));
+    format!("{prefix}{}", vec![tuple.as_str(); rows].join(", "))
+}
+
+/// Appends one row in PostgreSQL COPY text format.
+#[cfg(any(feature = "postgres", test))]
+fn encode_copy_text_row(row: &[BulkValue], out: &mut Vec<u8>) {
+    for (i, value) in row.iter().enumerate() {
+        if i > 0 {
+            out.push(b'\t');
+        }
+        match value {
+            BulkValue::Null => out.extend_from_slice(b"\\N"),
+            BulkValue::Bool(value) => out.push(if *value { b't' } else { b'f' }),
+            BulkValue::Int(value) => out.extend_from_slice(value.to_string().as_bytes()),
+            BulkValue::Float(value) => {
+                let text = if value.is_nan() {
+                    "NaN".to_string()
+                } else if value.is_infinite() {
+                    if value.is_sign_positive() {
+                        "Infinity".to_string()
+                    } else {
+                        "-Infinity".to_string()
+                    }
+                } else {
+                    value.to_string()
+                };
+                out.extend_from_slice(text.as_bytes());
+            }
+            BulkValue::Text(text) => escape_copy_text(text.as_bytes(), out),
+            BulkValue::Bytes(bytes) => {
+                // bytea hex input `\x..`, with the backslash escaped for COPY.
+                out.extend_from_slice(b"\\\\x");
+                for byte in bytes {
+                    out.extend_from_slice(format!("{byte:02x}").as_bytes());
+                }
+            }
+        }
+    }
+    out.push(b'\n');
+}
+
+#[cfg(any(feature = "postgres", test))]
+fn escape_copy_text(bytes: &[u8], out: &mut Vec<u8>) {
+    for &byte in bytes {
+        match byte {
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            _ => out.push(byte),
+        }
+    }
+}
+
+/// PostgreSQL target using `COPY ... FROM STDIN` (text format).
+///
+/// Each chunk is one COPY statement, so the server applies it atomically:
+/// `CommandComplete` confirms the whole chunk and `CopyFail` (or a dropped
+/// connection) discards it. Rows are batched into `CopyData` frames of
+/// roughly [`frame_bytes`](Self::frame_bytes) bytes.
+#[cfg(feature = "postgres")]
+#[derive(Debug)]
+pub struct PgCopyTarget<'a> {
+    conn: &'a mut PgConnection,
+    table: String,
+    frame_bytes: usize,
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PgCopyTarget<'a> {
+    /// Default `CopyData` frame size.
+    pub const DEFAULT_FRAME_BYTES: usize = 64 * 1024;
+
+    /// Creates a COPY target for `table` (optionally `schema.table`).
+    #[must_use]
+    pub fn new(conn: &'a mut PgConnection, table: impl Into<String>) -> Self {
+        Self {
+            conn,
+            table: table.into(),
+            frame_bytes: Self::DEFAULT_FRAME_BYTES,
+        }
+    }
+
+    /// Sets the target `CopyData` frame size in bytes (minimum 1).
+    #[must_use]
+    pub fn frame_bytes(mut self, bytes: usize) -> Self {
+        self.frame_bytes = bytes.max(1);
+        self
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl BulkTarget for PgCopyTarget<'_> {
+    type Error = PgError;
+
+    fn load_chunk(
+        &mut self,
+        cx: &Cx,
+        columns: &[String],
+        chunk: &BulkChunk,
+        meter: &mut ChunkMeter,
+    ) -> impl Future<Output = Outcome<Option<u64>, PgError>> + Send {
+        async move {
+            let sql = match quote_identifier(&self.table, '"').and_then(|table| {
+                let column_list = quoted_column_list(columns, '"')?;
+                Ok(format!("COPY {table} ({column_list}) FROM STDIN"))
+            }) {
+                Ok(sql) => sql,
+                Err(msg) => return Outcome::Err(PgError::Protocol(msg)),
+            };
+
+            let mut copy = match self.conn.copy_in(cx, &sql).await {
+                Outcome::Ok(copy) => copy,
+                Outcome::Err(err) => return Outcome::Err(err),
+                Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+            };
+
+            let mut frame = Vec::with_capacity(self.frame_bytes);
+            let mut frame_rows = 0usize;
+            let last = chunk.len().saturating_sub(1);
+            for (i, row) in chunk.rows().iter().enumerate() {
+                encode_copy_text_row(row, &mut frame);
+                frame_rows += 1;
+                if frame.len() < self.frame_bytes && i < last {
+                    continue;
+                }
+                // send_chunk answers cancellation with CopyFail itself, and a
+                // write error closes the connection, which aborts the COPY.
+                match copy.send_chunk(cx, &frame).await {
+                    Outcome::Ok(()) => meter.record(frame_rows, frame.len()),
+                    Outcome::Err(err) => return Outcome::Err(err),
+                    Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                    Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+                }
+                frame.clear();
+                frame_rows = 0;
+            }
+
+            match copy.finish(cx).await {
+                Outcome::Ok(done) => Outcome::Ok(Some(done.affected_rows())),
+                Outcome::Err(err) => Outcome::Err(err),
+                Outcome::Cancelled(reason) => Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => Outcome::Panicked(payload),
+            }
+        }
+    }
+}
+
+/// SQLite's historical default bound-parameter limit; staying under it keeps
+/// multi-row statements portable across builds.
+#[cfg(feature = "sqlite")]
+const SQLITE_MAX_BOUND_PARAMS: usize = 999;
+
+#[cfg(feature = "sqlite")]
+impl From<&BulkValue> for SqliteValue {
+    fn from(value: &BulkValue) -> Self {
+        match value {
+            BulkValue::Null => Self::Null,
+            BulkValue::Bool(value) => Self::Integer(i64::from(*value)),
+            BulkValue::Int(value) => Self::Integer(*value),
+            BulkValue::Float(value) => Self::Real(*value),
+            BulkValue::Text(value) => Self::Text(value.clone()),
+            BulkValue::Bytes(value) => Self::Blob(value.clone()),
+        }
+    }
+}
+
+/// SQLite target: batched multi-row `INSERT`s in one transaction per chunk.
+///
+/// The chunk transaction is opened with `BEGIN IMMEDIATE`; `COMMIT` confirms
+/// the chunk and any error or cancellation rolls it back.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteBulkTarget<'a> {
+    conn: &'a SqliteConnection,
+    table: String,
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> SqliteBulkTarget<'a> {
+    /// Creates an INSERT target for `table`.
+    #[must_use]
+    pub fn new(conn: &'a SqliteConnection, table: impl Into<String>) -> Self {
+        Self {
+            conn,
+            table: table.into(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl BulkTarget for SqliteBulkTarget<'_> {
+    type Error = SqliteError;
+
+    fn load_chunk(
+        &mut self,
+        cx: &Cx,
+        columns: &[String],
+        chunk: &BulkChunk,
+        meter: &mut ChunkMeter,
+    ) -> impl Future<Output = Outcome<Option<u64>, SqliteError>> + Send {
+        async move {
+            let width = columns.len();
+            let rows_per_statement = (SQLITE_MAX_BOUND_PARAMS / width.max(1)).max(1);
+            let prefix = match insert_prefix(&self.table, columns, '"') {
+                Ok(prefix) => prefix,
+                Err(msg) => return Outcome::Err(SqliteError::UnsafeSql(msg)),
+            };
+            let full_sql = multi_row_insert_sql(&prefix, width, rows_per_statement);
+
+            let tx = match self.conn.begin_immediate(cx).await {
+                Outcome::Ok(tx) => tx,
+                Outcome::Err(err) => return Outcome::Err(err),
+                Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+            };
+
+            let mut confirmed = 0u64;
+            for batch in chunk.rows().chunks(rows_per_statement) {
+                let partial_sql;
+                let sql = if batch.len() == rows_per_statement {
+                    &full_sql
+                } else {
+                    partial_sql = multi_row_insert_sql(&prefix, width, batch.len());
+                    &partial_sql
+                };
+                let params: Vec<SqliteValue> = batch.iter().flatten().map(Into::into).collect();
+                match tx.execute(cx, sql, &params).await {
+                    Outcome::Ok(affected) => {
+                        confirmed = confirmed.saturating_add(affected);
+                        let bytes = batch.iter().flatten().map(BulkValue::approx_len).sum();
+                        meter.record(batch.len(), bytes);
+                    }
+                    failure => return roll_back_sqlite_chunk(cx, tx, failure.map(Some)).await,
+                }
+            }
+
+            match crate::combinator::commit_section(cx, CHUNK_TERMINAL_MASKED_POLLS, tx.commit(cx))
+                .await
+            {
+                Outcome::Ok(()) => Outcome::Ok(Some(confirmed)),
+                Outcome::Err(err) => Outcome::Err(err),
+                Outcome::Cancelled(reason) => Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => Outcome::Panicked(payload),
+            }
+        }
+    }
+}
+
+/// Rolls back a failed SQLite chunk and returns the original failure.
+///
+/// A rollback that cannot run leaves the transaction poisoned, which still
+/// rolls it back before the connection is reused.
+#[cfg(feature = "sqlite")]
+async fn roll_back_sqlite_chunk(
+    cx: &Cx,
+    tx: SqliteTransaction<'_>,
+    failure: Outcome<Option<u64>, SqliteError>,
+) -> Outcome<Option<u64>, SqliteError> {
+    let _ =
+        crate::combinator::commit_section(cx, CHUNK_TERMINAL_MASKED_POLLS, tx.rollback(cx)).await;
+    failure
+}
+
+/// MySQL's prepared-statement placeholder limit.
+#[cfg(feature = "mysql")]
+const MYSQL_MAX_PLACEHOLDERS: usize = 65_535;
+
+/// Rows per prepared statement, also bounded to keep packets well under
+/// the default `max_allowed_packet`.
+#[cfg(feature = "mysql")]
+const MYSQL_MAX_ROWS_PER_STATEMENT: usize = 1_000;
+
+#[cfg(feature = "mysql")]
+fn mysql_param(value: &BulkValue) -> Box<dyn MySqlToSql + Send> {
+    match value {
+        BulkValue::Null => Box::new(None::<String>),
+        BulkValue::Bool(value) => Box::new(*value),
+        BulkValue::Int(value) => Box::new(*value),
+        BulkValue::Float(value) => Box::new(*value),
+        BulkValue::Text(value) => Box::new(value.clone()),
+        BulkValue::Bytes(value) => Box::new(value.clone()),
+    }
+}
+
+/// MySQL target: prepared multi-row `INSERT`s in one transaction per chunk.
+///
+/// `COMMIT` confirms the chunk and any error or cancellation rolls it back.
+/// See the module docs for why `LOAD DATA LOCAL INFILE` is not used.
+#[cfg(feature = "mysql")]
+#[derive(Debug)]
+pub struct MySqlBulkTarget<'a> {
+    conn: &'a mut MySqlConnection,
+    table: String,
+}
+
+#[cfg(feature = "mysql")]
+impl<'a> MySqlBulkTarget<'a> {
+    /// Creates an INSERT target for `table` (optionally `database.table`).
+    #[must_use]
+    pub fn new(conn: &'a mut MySqlConnection, table: impl Into<String>) -> Self {
+        Self {
+            conn,
+            table: table.into(),
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl BulkTarget for MySqlBulkTarget<'_> {
+    type Error = MySqlError;
+
+    fn load_chunk(
+        &mut self,
+        cx: &Cx,
+        columns: &[String],
+        chunk: &BulkChunk,
+        meter: &mut ChunkMeter,
+    ) -> impl Future<Output = Outcome<Option<u64>, MySqlError>> + Send {
+        async move {
+            let width = columns.len();
+            let rows_per_statement =
+                (MYSQL_MAX_PLACEHOLDERS / width.max(1)).clamp(1, MYSQL_MAX_ROWS_PER_STATEMENT);
+            let prefix = match insert_prefix(&self.table, columns, '`') {
+                Ok(prefix) => prefix,
+                Err(msg) => return Outcome::Err(MySqlError::Protocol(msg)),
+            };
+
+            let mut tx = match self.conn.begin(cx).await {
+                Outcome::Ok(tx) => tx,
+                Outcome::Err(err) => return Outcome::Err(err),
+                Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+            };
+
+            let mut confirmed = 0u64;
+            let mut prepared = None;
+            for batch in chunk.rows().chunks(rows_per_statement) {
+                let stmt = match prepared.take() {
+                    Some((rows, stmt)) if rows == batch.len() => stmt,
+                    _ => {
+                        let sql = multi_row_insert_sql(&prefix, width, batch.len());
+                        match tx.prepare(cx, &sql).await {
+                            Outcome::Ok(stmt) => stmt,
+                            failure => {
+                                return roll_back_mysql_chunk(cx, tx, failure.map(|_| None)).await;
+                            }
+                        }
+                    }
+                };
+                let owned: Vec<_> = batch.iter().flatten().map(mysql_param).collect();
+                let params: Vec<&dyn MySqlToSql> = owned
+                    .iter()
+                    .map(|param| param.as_ref() as &dyn MySqlToSql)
+                    .collect();
+                match tx.execute_prepared(cx, &stmt, &params).await {
+                    Outcome::Ok(affected) => {
+                        confirmed = confirmed.saturating_add(affected);
+                        let bytes = batch.iter().flatten().map(BulkValue::approx_len).sum();
+                        meter.record(batch.len(), bytes);
+                    }
+                    failure => return roll_back_mysql_chunk(cx, tx, failure.map(Some)).await,
+                }
+                prepared = Some((batch.len(), stmt));
+            }
+
+            match crate::combinator::commit_section(cx, CHUNK_TERMINAL_MASKED_POLLS, tx.commit(cx))
+                .await
+            {
+                Outcome::Ok(()) => Outcome::Ok(Some(confirmed)),
+                Outcome::Err(err) => Outcome::Err(err),
+                Outcome::Cancelled(reason) => Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => Outcome::Panicked(payload),
+            }
+        }
+    }
+}
+
+/// Rolls back a failed MySQL chunk and returns the original failure.
+#[cfg(feature = "mysql")]
+async fn roll_back_mysql_chunk(
+    cx: &Cx,
+    tx: MySqlTransaction<'_>,
+    failure: Outcome<Option<u64>, MySqlError>,
+) -> Outcome<Option<u64>, MySqlError> {
+    let _ =
+        crate::combinator::commit_section(cx, CHUNK_TERMINAL_MASKED_POLLS, tx.rollback(cx)).await;
+    failure
+}
+
+// ─── Raw input decoding ──────────────────────────────────────────────────────
+
+/// Encoding of raw input passed to [`BulkLoader::load_raw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkFormat {
+    /// RFC 4180 CSV. Quoted fields may span lines; an empty unquoted field
+    /// is NULL while `""` is the empty string.
+    Csv {
+        /// Field delimiter byte.
+        delimiter: u8,
+        /// Skip the first record as a header.
+        has_header: bool,
+    },
+    /// Newline-delimited JSON objects keyed by column name. Missing keys are
+    /// NULL; unknown keys and nested arrays/objects are malformed.
+    Ndjson,
+}
+
+impl BulkFormat {
+    /// Comma-delimited CSV with a header record.
+    #[must_use]
+    pub const fn csv() -> Self {
+        Self::Csv {
+            delimiter: b',',
+            has_header: true,
+        }
+    }
+
+    /// Newline-delimited JSON.
+    #[must_use]
+    pub const fn ndjson() -> Self {
+        Self::Ndjson
+    }
+}
+
+/// A decoded source record.
+#[derive(Debug, Clone, PartialEq)]
+enum Decoded {
+    Row { row: u64, values: Vec<BulkValue> },
+    Malformed(MalformedRow),
+}
+
+/// Incremental CSV/NDJSON record decoder.
+///
+/// Bytes may be pushed in arbitrary slices; a record is only decoded once
+/// its terminating newline (outside CSV quotes) has arrived.
+#[derive(Debug)]
+struct RecordDecoder {
+    format: BulkFormat,
+    columns: Vec<String>,
+    buf: Vec<u8>,
+    scan: usize,
+    in_quotes: bool,
+    header_pending: bool,
+    records: u64,
+}
+
+impl RecordDecoder {
+    fn new(format: BulkFormat, columns: &[String]) -> Self {
+        Self {
+            format,
+            columns: columns.to_vec(),
+            buf: Vec::new(),
+            scan: 0,
+            in_quotes: false,
+            header_pending: matches!(
+                format,
+                BulkFormat::Csv {
+                    has_header: true,
+                    ..
+                }
+            ),
+            records: 0,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Next complete record, or `None` until more bytes arrive.
+    fn next_record(&mut self) -> Option<Decoded> {
+        loop {
+            let end = self.find_record_end()?;
+            let record: Vec<u8> = self.buf.drain(..=end).collect();
+            if let Some(decoded) = self.decode(&record[..end]) {
+                return Some(decoded);
+            }
+        }
+    }
+
+    /// Decodes the trailing record once the input has ended.
+    fn finish(&mut self) -> Option<Decoded> {
+        let record = std::mem::take(&mut self.buf);
+        self.scan = 0;
+        if std::mem::take(&mut self.in_quotes) {
+            self.records += 1;
+            return Some(Decoded::Malformed(MalformedRow::new(
+                self.records,
+                "unterminated quoted field at end of input",
+                Some(&record),
+            )));
+        }
+        self.decode(&record)
+    }
+
+    fn find_record_end(&mut self) -> Option<usize> {
+        let quoting = matches!(self.format, BulkFormat::Csv { .. });
+        while let Some(&byte) = self.buf.get(self.scan) {
+            if quoting && byte == b'"' {
+                self.in_quotes = !self.in_quotes;
+            } else if byte == b'\n' && !self.in_quotes {
+                let end = self.scan;
+                self.scan = 0;
+                return Some(end);
+            }
+            self.scan += 1;
+        }
+        None
+    }
+
+    fn decode(&mut self, record: &[u8]) -> Option<Decoded> {
+        let record = record.strip_suffix(b"\r").unwrap_or(record);
+        if record.iter().all(u8::is_ascii_whitespace) {
+            return None;
+        }
+        if std::mem::take(&mut self.header_pending) {
+            return None;
+        }
+        self.records += 1;
+        let row = self.records;
+        let malformed = |reason: String| {
+            Some(Decoded::Malformed(MalformedRow::new(row, reason, Some(record))))
+        };
+
+        let Ok(text) = std::str::from_utf8(record) else {
+            return malformed("invalid UTF-8".to_string());
+        };
+        let values = match self.format {
+            BulkFormat::Csv { delimiter, .. } => {
+                parse_csv_record(text, delimiter, self.columns.len())
+            }
+            BulkFormat::Ndjson => parse_ndjson_object(text, &self.columns),
+        };
+        match values {
+            Ok(values) => Some(Decoded::Row { row, values }),
+            Err(reason) => malformed(reason),
+        }
+    }
+}
+
+/// Decodes one CSV record into exactly `width` values.
+fn parse_csv_record(line: &str, delimiter: u8, width: usize) -> Result<Vec<BulkValue>, String> {
+    let fields = parse_csv_fields(line, delimiter)?;
+    if fields.len() != width {
+        return Err(format!("expected {width} fields, found {}", fields.len()));
+    }
+    Ok(fields
+        .into_iter()
+        .map(|field| field.map_or(BulkValue::Null, BulkValue::Text))
+        .collect())
+}
+
+/// Splits one CSV record into fields; `None` marks an empty unquoted field.
+fn parse_csv_fields(line: &str, delimiter: u8) -> Result<Vec<Option<String>>, String> {
+    let bytes = line.as_bytes();
+    let mut fields = Vec::new();
+    let mut i = 0;
+    loop {
+        if bytes.get(i) == Some(&b'"') {
+            let mut field = Vec::new();
+            i += 1;
+            loop {
+                match bytes.get(i) {
+                    None => return Err("unterminated quoted field".to_string()),
+                    Some(b'"') if bytes.get(i + 1) == Some(&b'"') => {
+                        field.push(b'"');
+                        i += 2;
+                    }
+                    Some(b'"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&byte) => {
+                        field.push(byte);
+                        i += 1;
+                    }
+                }
+            }
+            // Only ASCII quote bytes were removed, so the field is still UTF-8.
+            fields.push(Some(String::from_utf8_lossy(&field).into_owned()));
+            match bytes.get(i) {
+                None => return Ok(fields),
+                Some(&byte) if byte == delimiter => i += 1,
+                Some(_) => {
+                    return Err(format!(
+                        "unexpected data after closing quote in field {}",
+                        fields.len()
+                    ));
+                }
+            }
+        } else {
+            let start = i;
+            while let Some(&byte) = bytes.get(i) {
+                if byte == delimiter {
+                    break;
+                }
+                if byte == b'"' {
+                    return Err(format!("bare quote in field {}", fields.len() + 1));
+                }
+                i += 1;
+            }
+            let field = &line[start..i];
+            fields.push((!field.is_empty()).then(|| field.to_string()));
+            if i >= bytes.len() {
+                return Ok(fields);
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Maps one NDJSON object onto the loader columns.
+fn parse_ndjson_object(line: &str, columns: &[String]) -> Result<Vec<BulkValue>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(line).map_err(|err| format!("invalid JSON: {err}"))?;
+    let serde_json::Value::Object(mut object) = value else {
+        return Err("expected a JSON object".to_string());
+    };
+    let mut values = Vec::with_capacity(columns.len());
+    for column in columns {
+        let value = match object.remove(column.as_str()) {
+            None | Some(serde_json::Value::Null) => BulkValue::Null,
+            Some(serde_json::Value::Bool(value)) => BulkValue::Bool(value),
+            Some(serde_json::Value::Number(number)) => {
+                if let Some(value) = number.as_i64() {
+                    BulkValue::Int(value)
+                } else if number.is_f64() {
+                    BulkValue::Float(number.as_f64().unwrap_or(f64::NAN))
+                } else {
+                    // u64 beyond i64::MAX: keep the exact digits.
+                    BulkValue::Text(number.to_string())
+                }
+            }
+            Some(serde_json::Value::String(value)) => BulkValue::Text(value),
+            Some(serde_json::Value::Array(_) | serde_json::Value::Object(_)) => {
+                return Err(format!("nested value for column {column:?}"));
+            }
+        };
+        values.push(value);
+    }
+    if let Some(unknown) = object.keys().next() {
+        return Err(format!("unknown column {unknown:?}"));
+    }
+    Ok(values)
+}
+
+// ─── Loader ──────────────────────────────────────────────────────────────────
+
+/// Final state of one chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkState {
+    /// The server confirmed the chunk; its rows are durable.
+    Committed,
+    /// The chunk was reserved and then aborted; none of its rows are durable.
+    RolledBack,
+    /// The chunk was buffered when the load stopped and never sent.
+    Unsent,
+}
+
+/// Outcome of one chunk in a [`BulkReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkRecord {
+    /// Zero-based chunk index.
+    pub index: usize,
+    /// Source row numbers covered by the chunk.
+    pub range: RowRange,
+    /// Rows in the chunk (excluding malformed rows inside `range`).
+    pub rows: u64,
+    /// Server-reported row count, when the protocol exposes one.
+    pub confirmed_rows: Option<u64>,
+    /// Final chunk state.
+    pub state: ChunkState,
+}
+
+/// Running totals for a bulk load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BulkProgress {
+    /// Source records read, including malformed ones.
+    pub rows_read: u64,
+    /// Rows written to the server.
+    pub rows_sent: u64,
+    /// Payload bytes written to the server.
+    pub bytes_sent: u64,
+    /// Rows the server reported as loaded, where the protocol exposes it.
+    pub rows_confirmed: u64,
+    /// Rows in committed chunks.
+    pub rows_committed: u64,
+    /// Rows in rolled-back chunks.
+    pub rows_rolled_back: u64,
+    /// Rows routed to the malformed-row sink.
+    pub malformed_rows: u64,
+    /// Chunks confirmed by the server.
+    pub chunks_committed: u64,
+    /// Chunks aborted after being reserved.
+    pub chunks_rolled_back: u64,
+}
+
+/// Why a bulk load stopped.
+#[derive(Debug)]
+pub enum BulkStop<E> {
+    /// The source was exhausted and every chunk committed.
+    Completed,
+    /// The load was cancelled.
+    Cancelled(CancelReason),
+    /// More than `limit` rows were malformed.
+    MalformedThreshold {
+        /// The configured limit.
+        limit: u64,
+    },
+    /// The target failed a chunk.
+    Failed(E),
+    /// The target panicked while loading a chunk.
+    Panicked(PanicPayload),
+}
+
+/// Result of a bulk load: how it stopped and what is durable.
+#[derive(Debug)]
+pub struct BulkReport<E> {
+    stop: BulkStop<E>,
+    chunks: Vec<ChunkRecord>,
+    progress: BulkProgress,
+}
+
+impl<E> BulkReport<E> {
+    /// Why the load stopped.
+    #[must_use]
+    pub const fn stop(&self) -> &BulkStop<E> {
+        &self.stop
+    }
+
+    /// Consumes the report, returning why the load stopped.
+    #[must_use]
+    pub fn into_stop(self) -> BulkStop<E> {
+        self.stop
+    }
+
+    /// Returns `true` when every source row was either committed or routed
+    /// to the malformed-row sink.
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        matches!(self.stop, BulkStop::Completed)
+    }
+
+    /// Final progress totals.
+    #[must_use]
+    pub const fn progress(&self) -> &BulkProgress {
+        &self.progress
+    }
+
+    /// Per-chunk outcomes in load order.
+    #[must_use]
+    pub fn chunks(&self) -> &[ChunkRecord] {
+        &self.chunks
+    }
+
+    /// Row ranges that are durable on the server, coalesced.
+    #[must_use]
+    pub fn durable_ranges(&self) -> Vec<RowRange> {
+        self.ranges_in(ChunkState::Committed)
+    }
+
+    /// Row ranges that were sent and rolled back, coalesced.
+    #[must_use]
+    pub fn rolled_back_ranges(&self) -> Vec<RowRange> {
+        self.ranges_in(ChunkState::RolledBack)
+    }
+
+    /// Row ranges that were buffered but never sent, coalesced.
+    #[must_use]
+    pub fn unsent_ranges(&self) -> Vec<RowRange> {
+        self.ranges_in(ChunkState::Unsent)
+    }
+
+    fn ranges_in(&self, state: ChunkState) -> Vec<RowRange> {
+        let mut ranges: Vec<RowRange> = Vec::new();
+        for chunk in self.chunks.iter().filter(|chunk| chunk.state == state) {
+            match ranges.last_mut() {
+                Some(last) if last.end == chunk.range.start => last.end = chunk.range.end,
+                _ => ranges.push(chunk.range),
+            }
+        }
+        ranges
+    }
+}
+
+type ProgressCallback = Box<dyn FnMut(&BulkProgress) + Send>;
+
+/// Chunked bulk loader with two-phase chunk commit.
+///
+/// See the [module docs](self) for the chunk lifecycle.
+pub struct BulkLoader {
+    columns: Vec<String>,
+    chunk_rows: usize,
+    max_malformed_rows: Option<u64>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for BulkLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkLoader")
+            .field("columns", &self.columns)
+            .field("chunk_rows", &self.chunk_rows)
+            .field("max_malformed_rows", &self.max_malformed_rows)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BulkLoader {
+    /// Creates a loader for the given target columns, in row order.
+    #[must_use]
+    pub fn new<I, S>(columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            columns: columns.into_iter().map(Into::into).collect(),
+            chunk_rows: DEFAULT_CHUNK_ROWS,
+            max_malformed_rows: None,
+            on_progress: None,
+        }
+    }
+
+    /// Sets the number of rows per chunk (minimum 1).
+    #[must_use]
+    pub fn chunk_rows(mut self, rows: usize) -> Self {
+        self.chunk_rows = rows.max(1);
+        self
+    }
+
+    /// Stops the load once more than `limit` rows have been malformed.
+    ///
+    /// `None` (the default) routes every malformed row to the sink without
+    /// stopping; `Some(0)` stops on the first one.
+    #[must_use]
+    pub const fn max_malformed_rows(mut self, limit: Option<u64>) -> Self {
+        self.max_malformed_rows = limit;
+        self
+    }
+
+    /// Registers a callback invoked after every malformed row and every
+    /// chunk outcome.
+    #[must_use]
+    pub fn on_progress(mut self, callback: impl FnMut(&BulkProgress) + Send + 'static) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Target columns, in row order.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Loads a stream of typed rows.
+    ///
+    /// Rows whose conversion fails, or whose width differs from the column
+    /// list, are routed to `sink`.
+    pub async fn load_rows<T, S, K>(
+        &mut self,
+        cx: &Cx,
+        target: &mut T,
+        mut rows: S,
+        sink: &mut K,
+    ) -> BulkReport<T::Error>
+    where
+        T: BulkTarget,
+        S: Stream + Unpin,
+        S::Item: IntoBulkRow,
+        K: MalformedRowSink + ?Sized,
+    {
+        let width = self.columns.len();
+        let mut run = LoadRun::new(self);
+        let mut row = 0u64;
+        loop {
+            if cx.checkpoint().is_err() {
+                return run.finish(BulkStop::Cancelled(cancelled_reason(cx)));
+            }
+            let Some(item) = rows.next().await else {
+                break;
+            };
+            row += 1;
+            let decoded = match item.into_bulk_row() {
+                Ok(values) if values.len() == width => Decoded::Row { row, values },
+                Ok(values) => Decoded::Malformed(MalformedRow::new(
+                    row,
+                    format!("expected {width} fields, found {}", values.len()),
+                    None,
+                )),
+                Err(reason) => Decoded::Malformed(MalformedRow::new(row, reason, None)),
+            };
+            if let Some(stop) = run.accept(cx, target, sink, decoded).await {
+                return run.finish(stop);
+            }
+        }
+        run.complete(cx, target).await
+    }
+
+    /// Loads raw CSV or NDJSON bytes, validating each record.
+    ///
+    /// Byte items may split records at any point, including inside quoted
+    /// CSV fields.
+    pub async fn load_raw<T, S, K>(
+        &mut self,
+        cx: &Cx,
+        target: &mut T,
+        format: BulkFormat,
+        mut input: S,
+        sink: &mut K,
+    ) -> BulkReport<T::Error>
+    where
+        T: BulkTarget,
+        S: Stream + Unpin,
+        S::Item: AsRef<[u8]>,
+        K: MalformedRowSink + ?Sized,
+    {
+        let mut decoder = RecordDecoder::new(format, &self.columns);
+        let mut run = LoadRun::new(self);
+        loop {
+            if cx.checkpoint().is_err() {
+                return run.finish(BulkStop::Cancelled(cancelled_reason(cx)));
+            }
+            let Some(bytes) = input.next().await else {
+                break;
+            };
+            decoder.push(bytes.as_ref());
+            while let Some(decoded) = decoder.next_record() {
+                if let Some(stop) = run.accept(cx, target, sink, decoded).await {
+                    return run.finish(stop);
+                }
+            }
+        }
+        if let Some(decoded) = decoder.finish()
+            && let Some(stop) = run.accept(cx, target, sink, decoded).await
+        {
+            return run.finish(stop);
+        }
+        run.complete(cx, target).await
+    }
+}
+
+/// State of one in-progress load.
+struct LoadRun<'a, E> {
+    loader: &'a mut BulkLoader,
+    chunks: Vec<ChunkRecord>,
+    progress: BulkProgress,
+    pending: Vec<Vec<BulkValue>>,
+    pending_range: Option<RowRange>,
+    _error: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<'a, E> LoadRun<'a, E> {
+    fn new(loader: &'a mut BulkLoader) -> Self {
+        let capacity = loader.chunk_rows.min(DEFAULT_CHUNK_ROWS);
+        Self {
+            loader,
+            chunks: Vec::new(),
+            progress: BulkProgress::default(),
+            pending: Vec::with_capacity(capacity),
+            pending_range: None,
+            _error: std::marker::PhantomData,
+        }
+    }
+
+    fn report_progress(&mut self) {
+        if let Some(callback) = self.loader.on_progress.as_mut() {
+            callback(&self.progress);
+        }
+    }
+
+    async fn accept<T, K>(
+        &mut self,
+        cx: &Cx,
+        target: &mut T,
+        sink: &mut K,
+        decoded: Decoded,
+    ) -> Option<BulkStop<E>>
+    where
+        T: BulkTarget<Error = E>,
+        K: MalformedRowSink + ?Sized,
+    {
+        self.progress.rows_read += 1;
+        match decoded {
+            Decoded::Malformed(row) => {
+                self.progress.malformed_rows += 1;
+                sink.route(row);
+                self.report_progress();
+                match self.loader.max_malformed_rows {
+                    Some(limit) if self.progress.malformed_rows > limit => {
+                        Some(BulkStop::MalformedThreshold { limit })
+                    }
+                    _ => None,
+                }
+            }
+            Decoded::Row { row, values } => {
+                self.pending.push(values);
+                let range = self.pending_range.get_or_insert(RowRange {
+                    start: row,
+                    end: row,
+                });
+                range.end = row + 1;
+                if self.pending.len() < self.loader.chunk_rows {
+                    return None;
+                }
+                if cx.checkpoint().is_err() {
+                    return Some(BulkStop::Cancelled(cancelled_reason(cx)));
+                }
+                self.flush(cx, target).await
+            }
+        }
+    }
+
+    /// Reserves the pending rows as a chunk and waits for its outcome.
+    async fn flush<T>(&mut self, cx: &Cx, target: &mut T) -> Option<BulkStop<E>>
+    where
+        T: BulkTarget<Error = E>,
+    {
+        let range = self.pending_range.take()?;
+        let chunk = BulkChunk {
+            index: self.chunks.len(),
+            range,
+            rows: std::mem::take(&mut self.pending),
+        };
+        let mut meter = ChunkMeter::default();
+        let outcome = target
+            .load_chunk(cx, &self.loader.columns, &chunk, &mut meter)
+            .await;
+
+        let rows = chunk.len() as u64;
+        self.progress.rows_sent += meter.rows_sent();
+        self.progress.bytes_sent += meter.bytes_sent();
+        let (state, confirmed_rows, stop) = match outcome {
+            Outcome::Ok(confirmed) => (ChunkState::Committed, confirmed, None),
+            Outcome::Err(err) => (ChunkState::RolledBack, None, Some(BulkStop::Failed(err))),
+            Outcome::Cancelled(reason) => (
+                ChunkState::RolledBack,
+                None,
+                Some(BulkStop::Cancelled(reason)),
+            ),
+            Outcome::Panicked(payload) => (
+                ChunkState::RolledBack,
+                None,
+                Some(BulkStop::Panicked(payload)),
+            ),
+        };
+        if state == ChunkState::Committed {
+            self.progress.rows_committed += rows;
+            self.progress.rows_confirmed += confirmed_rows.unwrap_or(0);
+            self.progress.chunks_committed += 1;
+        } else {
+            self.progress.rows_rolled_back += rows;
+            self.progress.chunks_rolled_back += 1;
+        }
+        self.chunks.push(ChunkRecord {
+            index: chunk.index,
+            range,
+            rows,
+            confirmed_rows,
+            state,
+        });
+        self.report_progress();
+        stop
+    }
+
+    async fn complete<T>(mut self, cx: &Cx, target: &mut T) -> BulkReport<E>
+    where
+        T: BulkTarget<Error = E>,
+    {
+        if self.pending_range.is_some() && cx.checkpoint().is_err() {
+            return self.finish(BulkStop::Cancelled(cancelled_reason(cx)));
+        }
+        let stop = self.flush(cx, target).await.unwrap_or(BulkStop::Completed);
+        self.finish(stop)
+    }
+
+    fn finish(mut self, stop: BulkStop<E>) -> BulkReport<E> {
+        if let Some(range) = self.pending_range.take() {
+            self.chunks.push(ChunkRecord {
+                index: self.chunks.len(),
+                range,
+                rows: self.pending.len() as u64,
+                confirmed_rows: None,
+                state: ChunkState::Unsent,
+            });
+            self.pending.clear();
+        }
+        BulkReport {
+            stop,
+            chunks: self.chunks,
+            progress: self.progress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::stream::iter;
+    use futures_lite::future::block_on;
+    use parking_lot::Mutex;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::task::{Context, Poll};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct MockError(String);
+
+    impl fmt::Display for MockError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    /// In-memory server: rows become visible only when a chunk commits.
+    #[derive(Debug, Default)]
+    struct MockTarget {
+        committed: Vec<Vec<BulkValue>>,
+        chunk_sizes: Vec<usize>,
+        frame_rows: usize,
+        /// Pending polls per frame, simulating a server that reads slowly.
+        slow_polls: u32,
+        fail_chunk: Option<usize>,
+        /// Request cancellation once this many rows have been sent in total.
+        cancel_after_rows: Option<u64>,
+        sent_rows: u64,
+        /// Source pull counter shared with a [`CountingStream`].
+        pulled: Option<Arc<AtomicU64>>,
+        max_buffered: u64,
+    }
+
+    impl MockTarget {
+        fn new() -> Self {
+            Self {
+                frame_rows: 2,
+                ..Self::default()
+            }
+        }
+    }
+
+    impl BulkTarget for MockTarget {
+        type Error = MockError;
+
+        fn load_chunk(
+            &mut self,
+            cx: &Cx,
+            _columns: &[String],
+            chunk: &BulkChunk,
+            meter: &mut ChunkMeter,
+        ) -> impl Future<Output = Outcome<Option<u64>, MockError>> + Send {
+            async move {
+                if let Some(pulled) = &self.pulled {
+                    let buffered = pulled.load(Ordering::SeqCst) - self.committed.len() as u64;
+                    self.max_buffered = self.max_buffered.max(buffered);
+                }
+                let mut staged = Vec::new();
+                for frame in chunk.rows().chunks(self.frame_rows) {
+                    if cx.checkpoint().is_err() {
+                        return Outcome::Cancelled(cancelled_reason(cx));
+                    }
+                    for _ in 0..self.slow_polls {
+                        crate::runtime::yield_now().await;
+                    }
+                    staged.extend(frame.iter().cloned());
+                    self.sent_rows += frame.len() as u64;
+                    meter.record(frame.len(), frame.len() * 10);
+                    if self.cancel_after_rows.is_some_and(|n| self.sent_rows >= n) {
+                        cx.set_cancel_requested(true);
+                    }
+                }
+                if self.fail_chunk == Some(chunk.index()) {
+                    return Outcome::Err(MockError(format!("chunk {} rejected", chunk.index())));
+                }
+                // Server confirmation: a cancel that arrived before the
+                // commit reply aborts the chunk.
+                if cx.checkpoint().is_err() {
+                    return Outcome::Cancelled(cancelled_reason(cx));
+                }
+                if let Some(pulled) = &self.pulled {
+                    let buffered = pulled.load(Ordering::SeqCst) - self.committed.len() as u64;
+                    self.max_buffered = self.max_buffered.max(buffered);
+                }
+                self.chunk_sizes.push(staged.len());
+                self.committed.extend(staged);
+                Outcome::Ok(Some(chunk.len() as u64))
+            }
+        }
+    }
+
+    struct CountingStream {
+        rows: std::vec::IntoIter<Vec<BulkValue>>,
+        pulled: Arc<AtomicU64>,
+    }
+
+    impl Stream for CountingStream {
+        type Item = Vec<BulkValue>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let next = self.rows.next();
+            if next.is_some() {
+                self.pulled.fetch_add(1, Ordering::SeqCst);
+            }
+            Poll::Ready(next)
+        }
+    }
+
+    fn rows(n: i64) -> Vec<Vec<BulkValue>> {
+        (1..=n)
+            .map(|i| vec![BulkValue::Int(i), BulkValue::Text(format!("r{i}"))])
+            .collect()
+    }
+
+    fn range(start: u64, end: u64) -> RowRange {
+        RowRange { start, end }
+    }
+
+    #[test]
+    fn chunk_boundaries_split_rows_exactly() {
+        init_test("chunk_boundaries_split_rows_exactly");
+        let cx = Cx::for_testing();
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_cb = Arc::clone(&seen);
+        let mut loader = BulkLoader::new(["id", "name"])
+            .chunk_rows(4)
+            .on_progress(move |progress| seen_in_cb.lock().push(*progress));
+
+        let report = block_on(loader.load_rows(&cx, &mut target, iter(rows(10)), &mut sink));
+
+        assert!(report.is_complete());
+        assert_eq!(target.chunk_sizes, vec![4, 4, 2]);
+        assert_eq!(target.committed, rows(10));
+        let ranges: Vec<_> = report.chunks().iter().map(|chunk| chunk.range).collect();
+        assert_eq!(ranges, vec![range(1, 5), range(5, 9), range(9, 11)]);
+        assert_eq!(report.durable_ranges(), vec![range(1, 11)]);
+        assert!(report.rolled_back_ranges().is_empty());
+        let progress = report.progress();
+        assert_eq!(progress.rows_sent, 10);
+        assert_eq!(progress.bytes_sent, 100);
+        assert_eq!(progress.rows_confirmed, 10);
+        assert_eq!(progress.chunks_committed, 3);
+        assert_eq!(seen.lock().len(), 3);
+        assert!(sink.is_empty());
+        crate::test_complete!("chunk_boundaries_split_rows_exactly");
+    }
+
+    #[test]
+    fn exact_multiple_of_chunk_size_leaves_no_empty_chunk() {
+        init_test("exact_multiple_of_chunk_size_leaves_no_empty_chunk");
+        let cx = Cx::for_testing();
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(3);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, iter(rows(6)), &mut sink));
+
+        assert!(report.is_complete());
+        assert_eq!(target.chunk_sizes, vec![3, 3]);
+        assert_eq!(report.chunks().len(), 2);
+        crate::test_complete!("exact_multiple_of_chunk_size_leaves_no_empty_chunk");
+    }
+
+    #[test]
+    fn cancellation_mid_chunk_reports_exact_durable_ranges() {
+        init_test("cancellation_mid_chunk_reports_exact_durable_ranges");
+        let cx = Cx::for_testing();
+        let mut target = MockTarget::new();
+        // Chunks of 4 with frames of 2: cancel after the first frame of the
+        // third chunk (rows 9-10) has reached the server.
+        target.cancel_after_rows = Some(10);
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(4);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, iter(rows(20)), &mut sink));
+
+        assert!(matches!(report.stop(), BulkStop::Cancelled(_)));
+        assert_eq!(report.durable_ranges(), vec![range(1, 9)]);
+        assert_eq!(report.rolled_back_ranges(), vec![range(9, 13)]);
+        assert!(report.unsent_ranges().is_empty());
+        assert_eq!(target.committed, rows(8));
+        let progress = report.progress();
+        assert_eq!(progress.rows_committed, 8);
+        assert_eq!(progress.rows_rolled_back, 4);
+        assert_eq!(progress.rows_sent, 10);
+        assert_eq!(progress.rows_read, 12);
+        crate::test_complete!("cancellation_mid_chunk_reports_exact_durable_ranges");
+    }
+
+    #[test]
+    fn cancellation_while_buffering_reports_unsent_rows() {
+        init_test("cancellation_while_buffering_reports_unsent_rows");
+        let cx = Cx::for_testing();
+        let cancel_cx = cx.clone();
+        let pulled = Arc::new(AtomicU64::new(0));
+        let source = CountingStream {
+            rows: rows(10).into_iter(),
+            pulled: Arc::clone(&pulled),
+        };
+        // Cancel from the source side after the sixth pull: chunk 1 (rows
+        // 1-4) is durable and rows 5-6 were buffered but never reserved.
+        let source = crate::stream::StreamExt::inspect(source, move |_| {
+            if pulled.load(Ordering::SeqCst) == 6 {
+                cancel_cx.set_cancel_requested(true);
+            }
+        });
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(4);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, source, &mut sink));
+
+        assert!(matches!(report.stop(), BulkStop::Cancelled(_)));
+        assert_eq!(report.durable_ranges(), vec![range(1, 5)]);
+        assert!(report.rolled_back_ranges().is_empty());
+        assert_eq!(report.unsent_ranges(), vec![range(5, 7)]);
+        assert_eq!(report.progress().rows_sent, 4);
+        crate::test_complete!("cancellation_while_buffering_reports_unsent_rows");
+    }
+
+    #[test]
+    fn target_error_rolls_back_only_the_failed_chunk() {
+        init_test("target_error_rolls_back_only_the_failed_chunk");
+        let cx = Cx::for_testing();
+        let mut target = MockTarget::new();
+        target.fail_chunk = Some(1);
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(3);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, iter(rows(9)), &mut sink));
+
+        match report.stop() {
+            BulkStop::Failed(err) => assert_eq!(err.0, "chunk 1 rejected"),
+            other => panic!("expected failure, got {other:?}"),
+        }
+        assert_eq!(report.durable_ranges(), vec![range(1, 4)]);
+        assert_eq!(report.rolled_back_ranges(), vec![range(4, 7)]);
+        assert_eq!(target.committed, rows(3));
+        crate::test_complete!("target_error_rolls_back_only_the_failed_chunk");
+    }
+
+    #[test]
+    fn malformed_typed_rows_are_routed_with_row_numbers() {
+        init_test("malformed_typed_rows_are_routed_with_row_numbers");
+        let cx = Cx::for_testing();
+        let source: Vec<Result<Vec<BulkValue>, String>> = vec![
+            Ok(vec![1i64.into(), "a".into()]),
+            Err("bad timestamp".to_string()),
+            Ok(vec![3i64.into()]),
+            Ok(vec![4i64.into(), None::<String>.into()]),
+        ];
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(10);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, iter(source), &mut sink));
+
+        assert!(report.is_complete());
+        let rejected: Vec<_> = sink.iter().map(|row| (row.row, row.reason.as_str())).collect();
+        assert_eq!(
+            rejected,
+            vec![(2, "bad timestamp"), (3, "expected 2 fields, found 1")]
+        );
+        assert_eq!(target.committed.len(), 2);
+        assert_eq!(target.committed[1], vec![BulkValue::Int(4), BulkValue::Null]);
+        assert_eq!(report.durable_ranges(), vec![range(1, 5)]);
+        assert_eq!(report.progress().malformed_rows, 2);
+        crate::test_complete!("malformed_typed_rows_are_routed_with_row_numbers");
+    }
+
+    #[test]
+    fn malformed_threshold_stops_load_and_keeps_committed_chunks() {
+        init_test("malformed_threshold_stops_load_and_keeps_committed_chunks");
+        let cx = Cx::for_testing();
+        let csv = "id,name\n1,a\n2,b\n3\n4,d\n5,\"e\n6,f,g\n7,h\n";
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"])
+            .chunk_rows(2)
+            .max_malformed_rows(Some(1));
+
+        let report = block_on(loader.load_raw(
+            &cx,
+            &mut target,
+            BulkFormat::csv(),
+            iter(vec![csv.as_bytes()]),
+            &mut sink,
+        ));
+
+        // Row 3 is the first malformed row; row 5 opens a quote that swallows
+        // the rest of the input and exceeds the limit of one.
+        assert!(matches!(
+            report.stop(),
+            BulkStop::MalformedThreshold { limit: 1 }
+        ));
+        assert_eq!(
+            sink.iter().map(|row| row.row).collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+        assert_eq!(report.durable_ranges(), vec![range(1, 3)]);
+        assert_eq!(report.unsent_ranges(), vec![range(4, 5)]);
+        assert_eq!(target.committed.len(), 2);
+        crate::test_complete!("malformed_threshold_stops_load_and_keeps_committed_chunks");
+    }
+
+    #[test]
+    fn csv_records_split_across_byte_chunks_decode_correctly() {
+        init_test("csv_records_split_across_byte_chunks_decode_correctly");
+        let cx = Cx::for_testing();
+        let csv: &[u8] =
+            b"id,name\r\n1,\"multi\nline, \"\"quoted\"\"\"\r\n2,\r\n3,\"\"\n\n4,bad\"quo\"te\n";
+        // Feed one byte at a time so every boundary lands mid-record.
+        let pieces: Vec<Vec<u8>> = csv.iter().map(|byte| vec![*byte]).collect();
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(2);
+
+        let report = block_on(loader.load_raw(
+            &cx,
+            &mut target,
+            BulkFormat::csv(),
+            iter(pieces),
+            &mut sink,
+        ));
+
+        assert!(report.is_complete());
+        let expected: Vec<Vec<BulkValue>> = vec![
+            vec!["1".into(), "multi\nline, \"quoted\"".into()],
+            vec!["2".into(), BulkValue::Null],
+            vec!["3".into(), "".into()],
+        ];
+        assert_eq!(target.committed, expected);
+        assert_eq!(sink.len(), 1);
+        assert_eq!(sink[0].row, 4);
+        assert_eq!(sink[0].reason, "bare quote in field 2");
+        assert_eq!(sink[0].raw.as_deref(), Some(&b"4,bad\"quo\"te"[..]));
+        crate::test_complete!("csv_records_split_across_byte_chunks_decode_correctly");
+    }
+
+    #[test]
+    fn ndjson_validation_routes_bad_lines() {
+        init_test("ndjson_validation_routes_bad_lines");
+        let cx = Cx::for_testing();
+        let input = concat!(
+            "{\"id\": 1, \"name\": \"a\", \"score\": 1.5}\n",
+            "[1, 2]\n",
+            "{\"id\": 3, \"extra\": true}\n",
+            "\n",
+            "{\"id\": 4, \"name\": {\"nested\": 1}}\n",
+            "not json\n",
+            "{\"id\": 18446744073709551615, \"name\": null}",
+        );
+        let mut target = MockTarget::new();
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name", "score"]);
+
+        let report = block_on(loader.load_raw(
+            &cx,
+            &mut target,
+            BulkFormat::ndjson(),
+            iter(vec![input.as_bytes()]),
+            &mut sink,
+        ));
+
+        assert!(report.is_complete());
+        let expected: Vec<Vec<BulkValue>> = vec![
+            vec![1i64.into(), "a".into(), 1.5f64.into()],
+            vec!["18446744073709551615".into(), BulkValue::Null, BulkValue::Null],
+        ];
+        assert_eq!(target.committed, expected);
+        let rejected: Vec<_> = sink.iter().map(|row| row.row).collect();
+        assert_eq!(rejected, vec![2, 3, 4, 5]);
+        assert!(sink[1].reason.contains("unknown column"));
+        assert!(sink[2].reason.contains("nested value"));
+        crate::test_complete!("ndjson_validation_routes_bad_lines");
+    }
+
+    #[test]
+    fn slow_server_applies_backpressure_to_the_source() {
+        init_test("slow_server_applies_backpressure_to_the_source");
+        let cx = Cx::for_testing();
+        let pulled = Arc::new(AtomicU64::new(0));
+        let source = CountingStream {
+            rows: rows(25).into_iter(),
+            pulled: Arc::clone(&pulled),
+        };
+        let mut target = MockTarget::new();
+        target.slow_polls = 8;
+        target.pulled = Some(Arc::clone(&pulled));
+        let mut sink: Vec<MalformedRow> = Vec::new();
+        let mut loader = BulkLoader::new(["id", "name"]).chunk_rows(5);
+
+        let report = block_on(loader.load_rows(&cx, &mut target, source, &mut sink));
+
+        assert!(report.is_complete());
+        assert_eq!(target.committed, rows(25));
+        // The source is never more than one chunk ahead of the server, even
+        // while the server yields repeatedly on every frame.
+        assert_eq!(target.max_buffered, 5);
+        assert_eq!(pulled.load(Ordering::SeqCst), 25);
+        crate::test_complete!("slow_server_applies_backpressure_to_the_source");
+    }
+
+    #[test]
+    fn copy_text_encoding_escapes_special_values() {
+        init_test("copy_text_encoding_escapes_special_values");
+        let mut out = Vec::new();
+        encode_copy_text_row(
+            &[
+                BulkValue::Null,
+                BulkValue::Bool(true),
+                BulkValue::Int(-7),
+                BulkValue::Float(f64::NEG_INFINITY),
+                BulkValue::Text("a\tb\\c\nd".to_string()),
+                BulkValue::Bytes(vec![0xde, 0xad]),
+            ],
+            &mut out,
+        );
+        assert_eq!(out, b"\\N\tt\t-7\t-Infinity\ta\\tb\\\\c\\nd\t\\\\xdead\n".to_vec());
+        crate::test_complete!("copy_text_encoding_escapes_special_values");
+    }
+
+    #[test]
+    fn identifiers_are_quoted_and_validated() {
+        init_test("identifiers_are_quoted_and_validated");
+        let columns = vec!["id".to_string(), "we\"ird".to_string()];
+        let prefix = insert_prefix("app.events", &columns, '"').unwrap();
+        assert_eq!(
+            multi_row_insert_sql(&prefix, columns.len(), 2),
+            "INSERT INTO \"app\".\"events\" (\"id\", \"we\"\"ird\") VALUES (?, ?), (?, ?)"
+        );
+        assert_eq!(quote_identifier("t`x", '`').unwrap(), "`t``x`");
+        assert!(quote_identifier("", '"').is_err());
+        assert!(quote_identifier("a..b", '"').is_err());
+        assert!(quote_identifier("nul\0", '"').is_err());
+        assert!(quoted_column_list(&["a.b".to_string()], '"').is_err());
+        crate::test_complete!("identifiers_are_quoted_and_validated");
+    }
+}
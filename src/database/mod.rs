@@ -9,6 +9,15 @@
 //! - [`postgres`]: PostgreSQL async client with wire protocol (requires `postgres` feature)
 //! - [`mysql`]: MySQL async client with wire protocol (requires `mysql` feature)
 //!
+//! [`bulk`] layers chunked CSV/NDJSON/typed-row ingestion with two-phase
+//! chunk commit on top of all three clients.
+//!
+//! [`outbox`] implements the transactional outbox: messages enqueued inside a
+//! database transaction and relayed to a messaging producer after commit.
+//!
+//! [`sqlite::maintenance`] runs managed WAL checkpoints as a region-owned
+//! task and retries `SQLITE_BUSY` with backoff on the runtime clock.
+//!
 //! # Design Philosophy
 //!
 //! Database clients integrate with [`Cx`] for checkpointing and cancellation.
@@ -36,12 +45,24 @@
 //! after the wire cancel has completed (or its connection-close fallback
 //! has been taken and logged).
 
+pub mod bulk;
+pub mod outbox;
 pub mod pool;
 pub mod transaction;
 
+pub use bulk::{
+    BulkChunk, BulkFormat, BulkLoader, BulkProgress, BulkReport, BulkStop, BulkTarget, BulkValue,
+    ChunkMeter, ChunkRecord, ChunkState, IntoBulkRow, MalformedRow, MalformedRowSink, RowRange,
+};
+pub use outbox::{
+    OUTBOX_MESSAGE_ID_HEADER, Outbox, OutboxConfig, OutboxConfigError, OutboxMessage,
+    OutboxMetrics, OutboxPass, OutboxPublisher, OutboxRecord, OutboxRelay, OutboxStore,
+    OutboxWriter,
+};
 pub use pool::{
-    AsyncConnectionManager, AsyncDbPool, AsyncPooledConnection, ConnectionManager, DbPool,
-    DbPoolConfig, DbPoolError, DbPoolStats, PooledConnection,
+    AsyncConnectionManager, AsyncDbPool, AsyncPooledConnection, CheckoutWaitHistogram,
+    ConnectionManager, DbPool, DbPoolConfig, DbPoolError, DbPoolStats, PoolMaintenanceReport,
+    PooledConnection,
 };
 
 #[cfg(feature = "sqlite")]
@@ -54,7 +75,12 @@ pub mod postgres;
 pub mod mysql;
 
 #[cfg(feature = "sqlite")]
-pub use sqlite::{SqliteConnection, SqliteError, SqliteRow, SqliteTransaction, SqliteValue};
+pub use sqlite::{
+    QuietWindow, SqliteBusyRetry, SqliteBusyRetryMetrics, SqliteConnection,
+    SqliteConnectionManager, SqliteError, SqliteRow, SqliteTransaction, SqliteValue,
+    WalCheckpointMode, WalCheckpointOutcome, WalCheckpointTrigger, WalMaintainer,
+    WalMaintenanceConfig, WalMaintenanceMetrics, WalMaintenancePass, WalStatus,
+};
 
 #[cfg(feature = "postgres")]
 pub use postgres::{
@@ -70,9 +96,13 @@ pub use mysql::{
     column_type as mysql_column_type,
 };
 
-/// Remaining wall/virtual time before the ambient [`Cx`] budget deadline.
+/// Remaining wall/virtual time before the effective [`Cx`] deadline.
+///
+/// The effective deadline ([`Cx::deadline`](crate::cx::Cx::deadline)) is the
+/// tighter of the budget deadline and any enclosing `timeout()`, so a query
+/// issued under a short timeout forwards that bound to the server.
 ///
-/// Returns `None` when the budget carries no deadline; returns
+/// Returns `None` when there is no deadline; returns
 /// `Some(Duration::ZERO)` when the deadline has already passed (the caller's
 /// own checkpoint will normally have observed that first). Uses the Cx's
 /// timer driver when available so lab runs stay on virtual time, falling
@@ -80,7 +110,7 @@ pub use mysql::{
 /// HTTP/gRPC clients (br-asupersync-server-stack-hardening-eeexl1.1.3).
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 pub(crate) fn remaining_budget(cx: &crate::cx::Cx) -> Option<std::time::Duration> {
-    let deadline = cx.budget().deadline?;
+    let deadline = cx.deadline()?;
     let now = cx
         .timer_driver()
         .map_or_else(crate::time::wall_now, |timer| timer.now());
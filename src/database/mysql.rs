@@ -5664,8 +5664,13 @@ impl crate::database::pool::AsyncConnectionManager for MySqlConnectionManager {
         MySqlConnection::connect_with_options(cx, self.options.clone()).await
     }
 
-    async fn is_valid(&self, _cx: &Cx, conn: &mut Self::Connection) -> bool {
-        !conn.inner.closed && !conn.in_transaction() && !conn.inner.needs_rollback
+    async fn is_valid(&self, cx: &Cx, conn: &mut Self::Connection) -> bool {
+        if conn.inner.closed || conn.in_transaction() || conn.inner.needs_rollback {
+            return false;
+        }
+        // COM_PING catches sockets the server closed while the connection
+        // sat idle (e.g. `wait_timeout` expiry).
+        matches!(conn.ping(cx).await, Outcome::Ok(()))
     }
 
     fn release_check(&self, conn: &mut Self::Connection) -> bool {
@@ -5676,6 +5681,17 @@ impl crate::database::pool::AsyncConnectionManager for MySqlConnectionManager {
         conn.invalidate_prepared_statements_for_pool_return();
         true
     }
+
+    /// Open transactions, including ones abandoned by a dropped
+    /// `MySqlTransaction`, are recoverable with a `ROLLBACK`.
+    fn needs_rollback(&self, conn: &Self::Connection) -> bool {
+        !conn.inner.closed && (conn.in_transaction() || conn.inner.needs_rollback)
+    }
+
+    async fn rollback(&self, _cx: &Cx, conn: &mut Self::Connection) -> bool {
+        conn.inner.needs_rollback = true;
+        conn.drain_abandoned_transaction().await.is_ok() && !conn.in_transaction()
+    }
 }
 
 // ============================================================================
@@ -0,0 +1,1404 @@
+//! Transactional outbox bridging database writes and messaging publishes.
+//!
+//! Writing a row and then publishing an event is a dual write: a crash between
+//! the two loses the event. The outbox closes that gap by making the event part
+//! of the database transaction:
+//!
+//! 1. [`Outbox::enqueue`] inserts the message into the outbox table inside the
+//!    caller's transaction, so it commits or rolls back with the business rows.
+//! 2. An [`OutboxRelay`] polls committed, unpublished rows in insertion order
+//!    and hands each one to an [`OutboxPublisher`].
+//! 3. A row is marked published only after the publisher reports the broker's
+//!    acknowledgement.
+//!
+//! A relay that crashes between steps 2 and 3 republishes the row on the next
+//! pass, so publication is at-least-once. Every published message carries
+//! its outbox id in the [`OUTBOX_MESSAGE_ID_HEADER`] header; consumers that
+//! need effectively-once processing record the ids they have applied and skip
+//! repeats.
+//!
+//! # Backends
+//!
+//! The database half is split into [`OutboxWriter`] (the caller's transaction)
+//! and [`OutboxStore`] (the relay's connection), implemented for PostgreSQL
+//! (requires `postgres` feature). The table layout comes from
+//! [`OutboxConfig::postgres_schema`]; run those statements from your
+//! migrations. The messaging half is [`OutboxPublisher`], implemented for
+//! [`KafkaProducer`]; configure the producer with [`Acks::All`] so the
+//! acknowledgement the relay waits for means the event is durable.
+//!
+//! # Ordering and leadership
+//!
+//! Rows are published in insertion order per ordering key (the
+//! [`ordering_key_column`](OutboxConfig::with_ordering_key_column)). When a
+//! publish fails, later rows with the same key wait for the next pass while
+//! other keys continue. Rows without a key are independent.
+//!
+//! Any number of relays may run against one table; a lease row elects a
+//! single leader and the others idle until the lease expires. Keep the lease
+//! longer than a pass takes, or a stalled leader and its successor may both
+//! publish (still at-least-once, but per-key order is no longer guaranteed).
+//!
+//! # Retention
+//!
+//! Published rows are deleted once they are older than
+//! [`OutboxConfig::with_retention`]. Unpublished rows are never deleted: when
+//! the broker is down they accumulate and [`OutboxMetrics::lag`] grows, but
+//! nothing is lost.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::database::outbox::{
+//!     Outbox, OutboxConfig, OutboxMessage, OutboxRelay, PgOutboxStore,
+//! };
+//!
+//! let outbox = Outbox::new(OutboxConfig::new("orders_outbox"))?;
+//!
+//! let mut tx = conn.begin(&cx).await?;
+//! tx.execute(&cx, "UPDATE orders SET state = 'paid' WHERE id = 7").await?;
+//! let message = OutboxMessage::new("order-events", payload).with_key("order-7");
+//! outbox.enqueue(&cx, &mut tx, message).await?;
+//! tx.commit(&cx).await?;
+//!
+//! // In a region that owns the relay:
+//! let relay = OutboxRelay::new(outbox, "relay-a");
+//! let mut store = PgOutboxStore::new(&mut relay_conn);
+//! relay.run(&cx, &mut store, &producer).await;
+//! ```
+//!
+//! [`KafkaProducer`]: crate::messaging::KafkaProducer
+//! [`Acks::All`]: crate::messaging::Acks::All
+
+use crate::cx::Cx;
+use crate::messaging::kafka::{KafkaError, KafkaProducer};
+use crate::time::{sleep, wall_now};
+use crate::types::{CancelReason, Outcome, Time};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+
+#[cfg(feature = "postgres")]
+use super::postgres::{PgConnection, PgError, PgRow, PgTransaction, ToSql as PgToSql};
+
+/// Header carrying the outbox message id on every published message.
+pub const OUTBOX_MESSAGE_ID_HEADER: &str = "outbox-message-id";
+
+macro_rules! try_outcome {
+    ($expr:expr) => {
+        match $expr {
+            Outcome::Ok(value) => value,
+            Outcome::Err(err) => return Outcome::Err(err),
+            Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+            Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+        }
+    };
+}
+
+fn cancelled_reason(cx: &Cx) -> CancelReason {
+    cx.cancel_reason().unwrap_or_default()
+}
+
+fn time_millis(time: Time) -> i64 {
+    i64::try_from(time.as_nanos() / 1_000_000).unwrap_or(i64::MAX)
+}
+
+fn duration_millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+// ─── Configuration ───────────────────────────────────────────────────────────
+
+/// Outbox table layout and relay tuning.
+#[derive(Clone)]
+pub struct OutboxConfig {
+    table: String,
+    ordering_key_column: String,
+    batch_size: usize,
+    poll_interval: Duration,
+    lease: Duration,
+    retention: Duration,
+    time_getter: Arc<dyn Fn() -> Time + Send + Sync>,
+}
+
+impl fmt::Debug for OutboxConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutboxConfig")
+            .field("table", &self.table)
+            .field("ordering_key_column", &self.ordering_key_column)
+            .field("batch_size", &self.batch_size)
+            .field("poll_interval", &self.poll_interval)
+            .field("lease", &self.lease)
+            .field("retention", &self.retention)
+            .field("time_getter", &"<fn>")
+            .finish()
+    }
+}
+
+impl OutboxConfig {
+    /// Default ordering key column.
+    pub const DEFAULT_ORDERING_KEY_COLUMN: &'static str = "aggregate_key";
+
+    /// Creates a configuration for the outbox table `table`
+    /// (optionally `schema.table`).
+    #[must_use]
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            ordering_key_column: Self::DEFAULT_ORDERING_KEY_COLUMN.to_string(),
+            batch_size: 100,
+            poll_interval: Duration::from_millis(500),
+            lease: Duration::from_secs(10),
+            retention: Duration::from_secs(24 * 60 * 60),
+            time_getter: Arc::new(wall_now),
+        }
+    }
+
+    /// Sets the column holding the per-aggregate ordering key.
+    #[must_use]
+    pub fn with_ordering_key_column(mut self, column: impl Into<String>) -> Self {
+        self.ordering_key_column = column.into();
+        self
+    }
+
+    /// Sets the maximum rows fetched per relay pass (minimum 1).
+    #[must_use]
+    pub fn with_batch_size(mut self, rows: usize) -> Self {
+        self.batch_size = rows.max(1);
+        self
+    }
+
+    /// Sets how long [`OutboxRelay::run`] waits between passes.
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets the relay leader lease duration.
+    #[must_use]
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Sets how long published rows are kept before cleanup deletes them.
+    #[must_use]
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Sets the clock used for row timestamps, leases, lag and retention.
+    #[must_use]
+    pub fn with_time_getter<F>(mut self, time_getter: F) -> Self
+    where
+        F: Fn() -> Time + Send + Sync + 'static,
+    {
+        self.time_getter = Arc::new(time_getter);
+        self
+    }
+
+    /// Outbox table name.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Lease table name (`<table>_leader`).
+    #[must_use]
+    pub fn leader_table(&self) -> String {
+        format!("{}_leader", self.table)
+    }
+
+    /// Ordering key column name.
+    #[must_use]
+    pub fn ordering_key_column(&self) -> &str {
+        &self.ordering_key_column
+    }
+
+    /// PostgreSQL DDL for the outbox and lease tables.
+    ///
+    /// The statements are idempotent (`IF NOT EXISTS`) and meant to be run
+    /// from the application's migrations.
+    #[must_use]
+    pub fn postgres_schema(&self) -> Vec<String> {
+        let table = &self.table;
+        let key = &self.ordering_key_column;
+        let index = table.replace('.', "_");
+        vec![
+            format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\
+                 id BIGSERIAL PRIMARY KEY, \
+                 message_id TEXT NOT NULL UNIQUE, \
+                 destination TEXT NOT NULL, \
+                 {key} TEXT, \
+                 payload BYTEA NOT NULL, \
+                 headers BYTEA NOT NULL, \
+                 created_at_ms BIGINT NOT NULL, \
+                 published_at_ms BIGINT)"
+            ),
+            format!(
+                "CREATE INDEX IF NOT EXISTS {index}_unpublished_idx \
+                 ON {table} (id) WHERE published_at_ms IS NULL"
+            ),
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (\
+                 name TEXT PRIMARY KEY, \
+                 holder TEXT NOT NULL, \
+                 expires_at_ms BIGINT NOT NULL)",
+                self.leader_table()
+            ),
+        ]
+    }
+
+    fn now(&self) -> Time {
+        (self.time_getter)()
+    }
+}
+
+/// An outbox table or column name that is not a plain SQL identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxConfigError {
+    /// The rejected identifier.
+    pub identifier: String,
+}
+
+impl fmt::Display for OutboxConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid outbox identifier {:?}", self.identifier)
+    }
+}
+
+impl std::error::Error for OutboxConfigError {}
+
+/// Accepts `[A-Za-z_][A-Za-z0-9_]*`, optionally dotted for `schema.table`.
+fn validate_identifier(name: &str, allow_dots: bool) -> Result<(), OutboxConfigError> {
+    let valid_part = |part: &str| {
+        part.chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+            && part.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    };
+    let valid = if allow_dots {
+        name.split('.').all(valid_part)
+    } else {
+        valid_part(name)
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(OutboxConfigError {
+            identifier: name.to_string(),
+        })
+    }
+}
+
+// ─── Messages ────────────────────────────────────────────────────────────────
+
+/// A message to enqueue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    /// Topic, subject or queue the relay publishes to.
+    pub destination: String,
+    /// Message payload.
+    pub payload: Vec<u8>,
+    /// Message headers.
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Ordering key; rows sharing a key are published in insertion order.
+    pub key: Option<String>,
+}
+
+impl OutboxMessage {
+    /// Creates a message for `destination`.
+    #[must_use]
+    pub fn new(destination: impl Into<String>, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            destination: destination.into(),
+            payload: payload.into(),
+            headers: Vec::new(),
+            key: None,
+        }
+    }
+
+    /// Sets the ordering key (usually the aggregate id).
+    #[must_use]
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Adds a header.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// One outbox row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxRecord {
+    /// Store-assigned sequence; insertion order. Ignored on insert.
+    pub id: i64,
+    /// Unique message id, published as [`OUTBOX_MESSAGE_ID_HEADER`].
+    pub message_id: String,
+    /// Topic, subject or queue the relay publishes to.
+    pub destination: String,
+    /// Ordering key.
+    pub key: Option<String>,
+    /// Message payload.
+    pub payload: Vec<u8>,
+    /// Message headers.
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Enqueue time in milliseconds on the outbox clock.
+    pub created_at_ms: i64,
+}
+
+/// Encodes headers as `u16 name length, name, u32 value length, value` runs.
+#[must_use]
+pub fn encode_headers(headers: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (name, value) in headers {
+        let name_len = u16::try_from(name.len()).unwrap_or(u16::MAX);
+        let value_len = u32::try_from(value.len()).unwrap_or(u32::MAX);
+        out.extend_from_slice(&name_len.to_be_bytes());
+        out.extend_from_slice(&name.as_bytes()[..usize::from(name_len)]);
+        out.extend_from_slice(&value_len.to_be_bytes());
+        out.extend_from_slice(&value[..value_len as usize]);
+    }
+    out
+}
+
+/// Decodes headers written by [`encode_headers`]; `None` if malformed.
+#[must_use]
+pub fn decode_headers(mut bytes: &[u8]) -> Option<Vec<(String, Vec<u8>)>> {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        if bytes.len() < len {
+            return None;
+        }
+        let (head, rest) = bytes.split_at(len);
+        *bytes = rest;
+        Some(head)
+    }
+
+    let mut headers = Vec::new();
+    while !bytes.is_empty() {
+        let name_len = u16::from_be_bytes(take(&mut bytes, 2)?.try_into().ok()?);
+        let name = std::str::from_utf8(take(&mut bytes, usize::from(name_len))?).ok()?;
+        let value_len = u32::from_be_bytes(take(&mut bytes, 4)?.try_into().ok()?);
+        let value = take(&mut bytes, value_len as usize)?;
+        headers.push((name.to_string(), value.to_vec()));
+    }
+    Some(headers)
+}
+
+// ─── Backend traits ──────────────────────────────────────────────────────────
+
+/// The caller's transaction: inserts outbox rows alongside business writes.
+pub trait OutboxWriter: Send {
+    /// Backend error type.
+    type Error: fmt::Display + Send;
+
+    /// Inserts `record` into the outbox table; the store assigns `id`.
+    fn insert_outbox(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        record: &OutboxRecord,
+    ) -> impl Future<Output = Outcome<(), Self::Error>> + Send;
+}
+
+/// The relay's view of the outbox table.
+pub trait OutboxStore: Send {
+    /// Backend error type.
+    type Error: fmt::Display + Send;
+
+    /// Takes or renews the leader lease for `holder` until `expires_at_ms`.
+    ///
+    /// Succeeds when no lease exists, the current lease is `holder`'s, or the
+    /// current lease expired before `now_ms`. Returns whether `holder` leads.
+    fn try_lead(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        holder: &str,
+        now_ms: i64,
+        expires_at_ms: i64,
+    ) -> impl Future<Output = Outcome<bool, Self::Error>> + Send;
+
+    /// Returns up to `limit` committed, unpublished rows in `id` order.
+    fn fetch_unpublished(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        limit: usize,
+    ) -> impl Future<Output = Outcome<Vec<OutboxRecord>, Self::Error>> + Send;
+
+    /// Marks `ids` published at `published_at_ms`.
+    fn mark_published(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        ids: &[i64],
+        published_at_ms: i64,
+    ) -> impl Future<Output = Outcome<(), Self::Error>> + Send;
+
+    /// Deletes rows published before `cutoff_ms`; returns how many.
+    fn delete_published_before(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        cutoff_ms: i64,
+    ) -> impl Future<Output = Outcome<u64, Self::Error>> + Send;
+}
+
+/// The messaging half: publishes one row and waits for the broker's ack.
+pub trait OutboxPublisher: Send + Sync {
+    /// Publisher error type.
+    type Error: fmt::Display + Send;
+
+    /// Publishes `record`, resolving `Ok` only once the broker acknowledged
+    /// it. Implementations must send [`OUTBOX_MESSAGE_ID_HEADER`].
+    fn publish(
+        &self,
+        cx: &Cx,
+        record: &OutboxRecord,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+impl OutboxPublisher for KafkaProducer {
+    type Error = KafkaError;
+
+    fn publish(
+        &self,
+        cx: &Cx,
+        record: &OutboxRecord,
+    ) -> impl Future<Output = Result<(), KafkaError>> + Send {
+        async move {
+            let mut headers: Vec<(&str, &[u8])> = record
+                .headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_slice()))
+                .collect();
+            headers.push((OUTBOX_MESSAGE_ID_HEADER, record.message_id.as_bytes()));
+            let key = record.key.as_deref().map(str::as_bytes);
+            self.send_with_headers(
+                cx,
+                &record.destination,
+                key,
+                &record.payload,
+                &headers,
+            )
+            .await
+            .map(|_| ())
+        }
+    }
+}
+
+// ─── Outbox ──────────────────────────────────────────────────────────────────
+
+/// Enqueues messages into the outbox table within the caller's transaction.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    config: OutboxConfig,
+}
+
+impl Outbox {
+    /// Creates an outbox, validating the configured table and column names.
+    pub fn new(config: OutboxConfig) -> Result<Self, OutboxConfigError> {
+        validate_identifier(&config.table, true)?;
+        validate_identifier(&config.ordering_key_column, false)?;
+        Ok(Self { config })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &OutboxConfig {
+        &self.config
+    }
+
+    /// Inserts `message` into the outbox inside `txn` and returns its
+    /// message id. The message is published only if `txn` commits.
+    pub async fn enqueue<W: OutboxWriter>(
+        &self,
+        cx: &Cx,
+        txn: &mut W,
+        message: OutboxMessage,
+    ) -> Outcome<String, W::Error> {
+        if cx.checkpoint().is_err() {
+            return Outcome::Cancelled(cancelled_reason(cx));
+        }
+        let mut id = [0_u8; 16];
+        cx.random_bytes(&mut id);
+        let message_id: String = id.iter().map(|byte| format!("{byte:02x}")).collect();
+        let record = OutboxRecord {
+            id: 0,
+            message_id,
+            destination: message.destination,
+            key: message.key,
+            payload: message.payload,
+            headers: message.headers,
+            created_at_ms: time_millis(self.config.now()),
+        };
+        try_outcome!(txn.insert_outbox(cx, &self.config, &record).await);
+        Outcome::Ok(record.message_id)
+    }
+}
+
+// ─── Relay ───────────────────────────────────────────────────────────────────
+
+/// Relay metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutboxMetrics {
+    /// Whether this relay held the lease on its last pass.
+    pub leader: bool,
+    /// Age of the oldest unpublished row seen on the last leading pass.
+    pub lag: Duration,
+    /// Unpublished rows seen on the last leading pass (capped by batch size).
+    pub pending: usize,
+    /// Rows published and marked.
+    pub published: u64,
+    /// Publish attempts the broker rejected or never acknowledged.
+    pub publish_failures: u64,
+    /// Published rows removed by retention cleanup.
+    pub cleaned: u64,
+    /// Passes that failed on a store error.
+    pub store_errors: u64,
+    /// Rows published per second over the interval since the previous pass.
+    pub publish_rate: f64,
+}
+
+/// Result of one relay pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxPass {
+    /// Whether this relay held the lease.
+    pub leader: bool,
+    /// Rows published and marked.
+    pub published: usize,
+    /// Rows whose publish failed.
+    pub failed: usize,
+    /// Rows held back behind a failed row with the same key.
+    pub blocked: usize,
+    /// Published rows removed by retention cleanup.
+    pub cleaned: u64,
+}
+
+#[derive(Debug, Default)]
+struct RelayState {
+    metrics: OutboxMetrics,
+    last_pass: Option<Time>,
+    last_error: Option<String>,
+}
+
+/// Publishes committed outbox rows; run one per process inside a region.
+#[derive(Debug, Clone)]
+pub struct OutboxRelay {
+    outbox: Outbox,
+    holder: String,
+    state: Arc<Mutex<RelayState>>,
+}
+
+impl OutboxRelay {
+    /// Creates a relay identified by `holder` in the lease table.
+    #[must_use]
+    pub fn new(outbox: Outbox, holder: impl Into<String>) -> Self {
+        Self {
+            outbox,
+            holder: holder.into(),
+            state: Arc::new(Mutex::new(RelayState::default())),
+        }
+    }
+
+    /// Returns a snapshot of the relay metrics.
+    #[must_use]
+    pub fn metrics(&self) -> OutboxMetrics {
+        self.state.lock().metrics
+    }
+
+    /// Returns the most recent store or publish error, if any.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.state.lock().last_error.clone()
+    }
+
+    /// Runs passes every poll interval until `cx` is cancelled.
+    ///
+    /// Store errors are counted in [`OutboxMetrics::store_errors`] and the
+    /// pass is retried on the next interval, so this returns only
+    /// `Cancelled` (or `Panicked` if a backend panicked).
+    pub async fn run<S, P>(&self, cx: &Cx, store: &mut S, publisher: &P) -> Outcome<(), S::Error>
+    where
+        S: OutboxStore,
+        P: OutboxPublisher,
+    {
+        loop {
+            match self.poll_once(cx, store, publisher).await {
+                Outcome::Ok(_) => {}
+                Outcome::Err(err) => {
+                    crate::tracing_compat::warn!(
+                        table = self.outbox.config.table(),
+                        error = %err,
+                        "outbox relay pass failed"
+                    );
+                    let mut state = self.state.lock();
+                    state.metrics.store_errors += 1;
+                    state.last_error = Some(err.to_string());
+                }
+                Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+            }
+            if let Err(reason) = wait_poll_interval(cx, self.outbox.config.poll_interval).await {
+                return Outcome::Cancelled(reason);
+            }
+        }
+    }
+
+    /// Runs one pass: takes the lease, publishes a batch, marks acknowledged
+    /// rows and cleans up rows past retention.
+    pub async fn poll_once<S, P>(
+        &self,
+        cx: &Cx,
+        store: &mut S,
+        publisher: &P,
+    ) -> Outcome<OutboxPass, S::Error>
+    where
+        S: OutboxStore,
+        P: OutboxPublisher,
+    {
+        let config = &self.outbox.config;
+        let now = config.now();
+        let now_ms = time_millis(now);
+        let expires_at_ms = now_ms.saturating_add(duration_millis(config.lease));
+        let leader = try_outcome!(
+            store
+                .try_lead(cx, config, &self.holder, now_ms, expires_at_ms)
+                .await
+        );
+        if !leader {
+            self.state.lock().metrics.leader = false;
+            return Outcome::Ok(OutboxPass::default());
+        }
+
+        let limit = config.batch_size;
+        let rows = try_outcome!(store.fetch_unpublished(cx, config, limit).await);
+        let lag = rows.first().map_or(Duration::ZERO, |row| {
+            let age = now_ms.saturating_sub(row.created_at_ms).max(0);
+            Duration::from_millis(age.unsigned_abs())
+        });
+
+        let mut pass = OutboxPass {
+            leader: true,
+            ..OutboxPass::default()
+        };
+        let mut acked = Vec::new();
+        let mut last_error = None;
+        let mut blocked_keys: HashSet<&str> = HashSet::new();
+        for row in &rows {
+            if cx.checkpoint().is_err() {
+                return Outcome::Cancelled(cancelled_reason(cx));
+            }
+            let key = row.key.as_deref();
+            if key.is_some_and(|key| blocked_keys.contains(key)) {
+                pass.blocked += 1;
+                continue;
+            }
+            match publisher.publish(cx, row).await {
+                Ok(()) => acked.push(row.id),
+                Err(err) => {
+                    pass.failed += 1;
+                    crate::tracing_compat::debug!(
+                        message_id = %row.message_id,
+                        error = %err,
+                        "outbox publish failed"
+                    );
+                    last_error = Some(err.to_string());
+                    if let Some(key) = key {
+                        blocked_keys.insert(key);
+                    }
+                }
+            }
+        }
+        if !acked.is_empty() {
+            let published_at_ms = time_millis(config.now());
+            try_outcome!(
+                store
+                    .mark_published(cx, config, &acked, published_at_ms)
+                    .await
+            );
+        }
+        pass.published = acked.len();
+
+        let cutoff_ms = now_ms.saturating_sub(duration_millis(config.retention));
+        pass.cleaned = try_outcome!(store.delete_published_before(cx, config, cutoff_ms).await);
+
+        let mut state = self.state.lock();
+        let elapsed = state.last_pass.map(|last| now.duration_since(last));
+        state.last_pass = Some(now);
+        let metrics = &mut state.metrics;
+        metrics.leader = true;
+        metrics.lag = lag;
+        metrics.pending = rows.len() - pass.published;
+        metrics.published += pass.published as u64;
+        metrics.publish_failures += pass.failed as u64;
+        metrics.cleaned += pass.cleaned;
+        metrics.publish_rate = elapsed.map_or(0.0, |nanos| per_second(pass.published, nanos));
+        if last_error.is_some() {
+            state.last_error = last_error;
+        }
+        drop(state);
+        Outcome::Ok(pass)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // Rates are approximate by nature.
+fn per_second(count: usize, nanos: u64) -> f64 {
+    if nanos == 0 {
+        return 0.0;
+    }
+    count as f64 * 1e9 / nanos as f64
+}
+
+async fn wait_poll_interval(cx: &Cx, interval: Duration) -> Result<(), CancelReason> {
+    let now = cx
+        .timer_driver()
+        .map_or_else(wall_now, |driver| driver.now());
+    let mut sleeper = sleep(now, interval);
+    poll_fn(|task_cx| {
+        if cx.checkpoint().is_err() {
+            return Poll::Ready(Err(cancelled_reason(cx)));
+        }
+        Pin::new(&mut sleeper).poll(task_cx).map(|()| Ok(()))
+    })
+    .await
+}
+
+// ─── PostgreSQL ──────────────────────────────────────────────────────────────
+
+#[cfg(feature = "postgres")]
+impl OutboxWriter for PgTransaction<'_> {
+    type Error = PgError;
+
+    fn insert_outbox(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        record: &OutboxRecord,
+    ) -> impl Future<Output = Outcome<(), PgError>> + Send {
+        let sql = format!(
+            "INSERT INTO {} (message_id, destination, {}, payload, headers, created_at_ms) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+            config.table(),
+            config.ordering_key_column()
+        );
+        let headers = encode_headers(&record.headers);
+        async move {
+            let params: [&dyn PgToSql; 6] = [
+                &record.message_id,
+                &record.destination,
+                &record.key,
+                &record.payload,
+                &headers,
+                &record.created_at_ms,
+            ];
+            try_outcome!(self.execute_params(cx, &sql, &params).await);
+            Outcome::Ok(())
+        }
+    }
+}
+
+/// PostgreSQL [`OutboxStore`] over a dedicated relay connection.
+#[cfg(feature = "postgres")]
+#[derive(Debug)]
+pub struct PgOutboxStore<'a> {
+    conn: &'a mut PgConnection,
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PgOutboxStore<'a> {
+    /// Creates a store over `conn`.
+    #[must_use]
+    pub fn new(conn: &'a mut PgConnection) -> Self {
+        Self { conn }
+    }
+}
+
+#[cfg(feature = "postgres")]
+fn pg_outbox_record(row: &PgRow, key_column: &str) -> Result<OutboxRecord, PgError> {
+    let headers: Vec<u8> = row.get_typed("headers")?;
+    Ok(OutboxRecord {
+        id: row.get_i64("id")?,
+        message_id: row.get_str("message_id")?.to_string(),
+        destination: row.get_str("destination")?.to_string(),
+        key: row.get_typed(key_column)?,
+        payload: row.get_typed("payload")?,
+        headers: decode_headers(&headers)
+            .ok_or_else(|| PgError::Protocol("malformed outbox headers".to_string()))?,
+        created_at_ms: row.get_i64("created_at_ms")?,
+    })
+}
+
+#[cfg(feature = "postgres")]
+impl OutboxStore for PgOutboxStore<'_> {
+    type Error = PgError;
+
+    fn try_lead(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        holder: &str,
+        now_ms: i64,
+        expires_at_ms: i64,
+    ) -> impl Future<Output = Outcome<bool, PgError>> + Send {
+        let leader = config.leader_table();
+        let sql = format!(
+            "INSERT INTO {leader} (name, holder, expires_at_ms) VALUES ($1, $2, $3) \
+             ON CONFLICT (name) DO UPDATE \
+             SET holder = EXCLUDED.holder, expires_at_ms = EXCLUDED.expires_at_ms \
+             WHERE {leader}.holder = EXCLUDED.holder OR {leader}.expires_at_ms < $4"
+        );
+        let name = config.table().to_string();
+        async move {
+            let params: [&dyn PgToSql; 4] = [&name, &holder, &expires_at_ms, &now_ms];
+            let affected = try_outcome!(self.conn.execute_params(cx, &sql, &params).await);
+            Outcome::Ok(affected > 0)
+        }
+    }
+
+    fn fetch_unpublished(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        limit: usize,
+    ) -> impl Future<Output = Outcome<Vec<OutboxRecord>, PgError>> + Send {
+        let key_column = config.ordering_key_column().to_string();
+        let sql = format!(
+            "SELECT id, message_id, destination, {key_column}, payload, headers, created_at_ms \
+             FROM {} WHERE published_at_ms IS NULL ORDER BY id LIMIT $1",
+            config.table()
+        );
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        async move {
+            let rows = try_outcome!(self.conn.query_params(cx, &sql, &[&limit]).await);
+            let records: Result<Vec<_>, _> = rows
+                .iter()
+                .map(|row| pg_outbox_record(row, &key_column))
+                .collect();
+            records.into()
+        }
+    }
+
+    fn mark_published(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        ids: &[i64],
+        published_at_ms: i64,
+    ) -> impl Future<Output = Outcome<(), PgError>> + Send {
+        // Ids are store-assigned integers, so inlining them is injection-safe.
+        let id_list: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        let sql = format!(
+            "UPDATE {} SET published_at_ms = $1 WHERE id IN ({})",
+            config.table(),
+            id_list.join(", ")
+        );
+        async move {
+            let params: [&dyn PgToSql; 1] = [&published_at_ms];
+            try_outcome!(self.conn.execute_params(cx, &sql, &params).await);
+            Outcome::Ok(())
+        }
+    }
+
+    fn delete_published_before(
+        &mut self,
+        cx: &Cx,
+        config: &OutboxConfig,
+        cutoff_ms: i64,
+    ) -> impl Future<Output = Outcome<u64, PgError>> + Send {
+        let sql = format!(
+            "DELETE FROM {} WHERE published_at_ms IS NOT NULL AND published_at_ms < $1",
+            config.table()
+        );
+        async move { self.conn.execute_params(cx, &sql, &[&cutoff_ms]).await }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use futures_lite::future::block_on;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct MockError(String);
+
+    impl fmt::Display for MockError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct StoredRow {
+        record: OutboxRecord,
+        published_at_ms: Option<i64>,
+    }
+
+    /// In-memory database: rows become visible only when a transaction commits.
+    #[derive(Debug, Default)]
+    struct MockDb {
+        rows: Vec<StoredRow>,
+        next_id: i64,
+        lease: Option<(String, i64)>,
+        fail_next_mark: bool,
+    }
+
+    type SharedDb = Arc<Mutex<MockDb>>;
+
+    struct MockTxn {
+        db: SharedDb,
+        pending: Vec<OutboxRecord>,
+    }
+
+    impl MockTxn {
+        fn begin(db: &SharedDb) -> Self {
+            Self {
+                db: Arc::clone(db),
+                pending: Vec::new(),
+            }
+        }
+
+        fn commit(self) {
+            let mut db = self.db.lock();
+            for mut record in self.pending {
+                db.next_id += 1;
+                record.id = db.next_id;
+                db.rows.push(StoredRow {
+                    record,
+                    published_at_ms: None,
+                });
+            }
+        }
+    }
+
+    impl OutboxWriter for MockTxn {
+        type Error = MockError;
+
+        fn insert_outbox(
+            &mut self,
+            _cx: &Cx,
+            _config: &OutboxConfig,
+            record: &OutboxRecord,
+        ) -> impl Future<Output = Outcome<(), MockError>> + Send {
+            self.pending.push(record.clone());
+            std::future::ready(Outcome::Ok(()))
+        }
+    }
+
+    struct MockStore {
+        db: SharedDb,
+    }
+
+    impl OutboxStore for MockStore {
+        type Error = MockError;
+
+        fn try_lead(
+            &mut self,
+            _cx: &Cx,
+            _config: &OutboxConfig,
+            holder: &str,
+            now_ms: i64,
+            expires_at_ms: i64,
+        ) -> impl Future<Output = Outcome<bool, MockError>> + Send {
+            let mut db = self.db.lock();
+            let available = db
+                .lease
+                .as_ref()
+                .is_none_or(|(current, expires)| current == holder || *expires < now_ms);
+            if available {
+                db.lease = Some((holder.to_string(), expires_at_ms));
+            }
+            std::future::ready(Outcome::Ok(available))
+        }
+
+        fn fetch_unpublished(
+            &mut self,
+            _cx: &Cx,
+            _config: &OutboxConfig,
+            limit: usize,
+        ) -> impl Future<Output = Outcome<Vec<OutboxRecord>, MockError>> + Send {
+            let rows = self
+                .db
+                .lock()
+                .rows
+                .iter()
+                .filter(|row| row.published_at_ms.is_none())
+                .take(limit)
+                .map(|row| row.record.clone())
+                .collect();
+            std::future::ready(Outcome::Ok(rows))
+        }
+
+        fn mark_published(
+            &mut self,
+            _cx: &Cx,
+            _config: &OutboxConfig,
+            ids: &[i64],
+            published_at_ms: i64,
+        ) -> impl Future<Output = Outcome<(), MockError>> + Send {
+            let mut db = self.db.lock();
+            if std::mem::take(&mut db.fail_next_mark) {
+                return std::future::ready(Outcome::Err(MockError("connection lost".into())));
+            }
+            for row in &mut db.rows {
+                if ids.contains(&row.record.id) {
+                    row.published_at_ms = Some(published_at_ms);
+                }
+            }
+            std::future::ready(Outcome::Ok(()))
+        }
+
+        fn delete_published_before(
+            &mut self,
+            _cx: &Cx,
+            _config: &OutboxConfig,
+            cutoff_ms: i64,
+        ) -> impl Future<Output = Outcome<u64, MockError>> + Send {
+            let mut db = self.db.lock();
+            let before = db.rows.len();
+            let keep = |row: &StoredRow| row.published_at_ms.is_none_or(|at| at >= cutoff_ms);
+            db.rows.retain(keep);
+            std::future::ready(Outcome::Ok((before - db.rows.len()) as u64))
+        }
+    }
+
+    /// Broker that acknowledges while up, optionally rejecting chosen payloads
+    /// a number of times.
+    #[derive(Debug, Default)]
+    struct MockBroker {
+        down: bool,
+        reject: Vec<(Vec<u8>, u32)>,
+        /// `(key, payload, message id)` in acknowledgement order.
+        acked: Vec<(Option<String>, Vec<u8>, String)>,
+    }
+
+    #[derive(Clone, Default)]
+    struct MockPublisher {
+        broker: Arc<Mutex<MockBroker>>,
+    }
+
+    impl MockPublisher {
+        fn acked_payloads(&self) -> Vec<Vec<u8>> {
+            let broker = self.broker.lock();
+            broker
+                .acked
+                .iter()
+                .map(|(_, payload, _)| payload.clone())
+                .collect()
+        }
+    }
+
+    impl OutboxPublisher for MockPublisher {
+        type Error = MockError;
+
+        fn publish(
+            &self,
+            _cx: &Cx,
+            record: &OutboxRecord,
+        ) -> impl Future<Output = Result<(), MockError>> + Send {
+            let mut guard = self.broker.lock();
+            let broker = &mut *guard;
+            let rejected = broker
+                .reject
+                .iter_mut()
+                .find(|(payload, remaining)| *payload == record.payload && *remaining > 0);
+            let result = if broker.down {
+                Err(MockError("broker unavailable".into()))
+            } else if let Some((_, remaining)) = rejected {
+                *remaining -= 1;
+                Err(MockError("not leader for partition".into()))
+            } else {
+                broker.acked.push((
+                    record.key.clone(),
+                    record.payload.clone(),
+                    record.message_id.clone(),
+                ));
+                Ok(())
+            };
+            std::future::ready(result)
+        }
+    }
+
+    fn virtual_clock() -> (Arc<AtomicU64>, OutboxConfig) {
+        let now = Arc::new(AtomicU64::new(1_000_000_000_000));
+        let clock = Arc::clone(&now);
+        let config = OutboxConfig::new("app.outbox")
+            .with_time_getter(move || Time::from_nanos(clock.load(Ordering::SeqCst)));
+        (now, config)
+    }
+
+    fn advance(now: &AtomicU64, by: Duration) {
+        now.fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    fn enqueue_committed(cx: &Cx, outbox: &Outbox, db: &SharedDb, messages: &[(&str, &str)]) {
+        let mut txn = MockTxn::begin(db);
+        for (key, payload) in messages {
+            let message = OutboxMessage::new("events", payload.as_bytes()).with_key(*key);
+            block_on(outbox.enqueue(cx, &mut txn, message)).unwrap();
+        }
+        txn.commit();
+    }
+
+    fn pass(relay: &OutboxRelay, cx: &Cx, db: &SharedDb, publisher: &MockPublisher) -> OutboxPass {
+        let mut store = MockStore { db: Arc::clone(db) };
+        block_on(relay.poll_once(cx, &mut store, publisher)).unwrap()
+    }
+
+    #[test]
+    fn crash_between_commit_and_publish_publishes_exactly_committed_rows() {
+        init_test("crash_between_commit_and_publish_publishes_exactly_committed_rows");
+        let cx = Cx::for_testing();
+        let (_now, config) = virtual_clock();
+        let outbox = Outbox::new(config).unwrap();
+        let db = SharedDb::default();
+        let publisher = MockPublisher::default();
+
+        enqueue_committed(&cx, &outbox, &db, &[("order-1", "paid"), ("order-2", "paid")]);
+        // A transaction that never commits leaves nothing behind.
+        let mut aborted = MockTxn::begin(&db);
+        let message = OutboxMessage::new("events", "refunded").with_key("order-1");
+        block_on(outbox.enqueue(&cx, &mut aborted, message)).unwrap();
+        drop(aborted);
+
+        // The first relay "crashes" after the broker acknowledged but before
+        // the rows were marked.
+        db.lock().fail_next_mark = true;
+        let crashed = OutboxRelay::new(outbox.clone(), "relay-a");
+        let mut store = MockStore {
+            db: Arc::clone(&db),
+        };
+        let outcome = block_on(crashed.poll_once(&cx, &mut store, &publisher));
+        crate::assert_with_log!(outcome.is_err(), "mark failed", true, outcome.is_err());
+
+        // Its successor republishes the same committed rows under the same ids.
+        db.lock().lease = None;
+        let relay = OutboxRelay::new(outbox, "relay-b");
+        let first = pass(&relay, &cx, &db, &publisher);
+        crate::assert_with_log!(first.published == 2, "published", 2, first.published);
+        let second = pass(&relay, &cx, &db, &publisher);
+        crate::assert_with_log!(second.published == 0, "nothing left", 0, second.published);
+
+        let broker = publisher.broker.lock();
+        let payloads: Vec<&[u8]> = broker
+            .acked
+            .iter()
+            .map(|(_, payload, _)| payload.as_slice())
+            .collect();
+        let expected = vec![&b"paid"[..]; 4];
+        crate::assert_with_log!(payloads == expected, "payloads", expected, payloads);
+        let ids: Vec<&str> = broker
+            .acked
+            .iter()
+            .map(|(_, _, id)| id.as_str())
+            .collect();
+        crate::assert_with_log!(ids[..2] == ids[2..], "duplicate ids", &ids[..2], &ids[2..]);
+        crate::assert_with_log!(ids[0] != ids[1], "unique ids", true, ids[0] != ids[1]);
+        drop(broker);
+        let unpublished = db
+            .lock()
+            .rows
+            .iter()
+            .filter(|row| row.published_at_ms.is_none())
+            .count();
+        crate::assert_with_log!(unpublished == 0, "all marked", 0, unpublished);
+        crate::test_complete!("crash_between_commit_and_publish_publishes_exactly_committed_rows");
+    }
+
+    #[test]
+    fn concurrent_relays_preserve_per_key_order_under_one_leader() {
+        init_test("concurrent_relays_preserve_per_key_order_under_one_leader");
+        let cx = Cx::for_testing();
+        let (now, config) = virtual_clock();
+        let outbox = Outbox::new(config.with_lease(Duration::from_secs(10))).unwrap();
+        let db = SharedDb::default();
+        let publisher = MockPublisher::default();
+        publisher.broker.lock().reject.push((b"b1".to_vec(), 1));
+
+        enqueue_committed(
+            &cx,
+            &outbox,
+            &db,
+            &[("a", "a1"), ("b", "b1"), ("a", "a2"), ("b", "b2"), ("a", "a3")],
+        );
+        let relay_a = OutboxRelay::new(outbox.clone(), "relay-a");
+        let relay_b = OutboxRelay::new(outbox.clone(), "relay-b");
+
+        let lead = pass(&relay_a, &cx, &db, &publisher);
+        let idle = pass(&relay_b, &cx, &db, &publisher);
+        let leaders = (lead.leader, idle.leader);
+        crate::assert_with_log!(leaders == (true, false), "one leader", (true, false), leaders);
+        crate::assert_with_log!(lead.published == 3, "a rows", 3, lead.published);
+        crate::assert_with_log!(lead.blocked == 1, "b2 waits for b1", 1, lead.blocked);
+        crate::assert_with_log!(idle.published == 0, "follower idle", 0, idle.published);
+
+        let retry = pass(&relay_a, &cx, &db, &publisher);
+        crate::assert_with_log!(retry.published == 2, "b rows", 2, retry.published);
+
+        // The leader stops renewing; the follower takes over after the lease.
+        enqueue_committed(&cx, &outbox, &db, &[("a", "a4")]);
+        advance(&now, Duration::from_secs(11));
+        let takeover = pass(&relay_b, &cx, &db, &publisher);
+        crate::assert_with_log!(takeover.leader, "takeover", true, takeover.leader);
+        crate::assert_with_log!(takeover.published == 1, "a4", 1, takeover.published);
+        let demoted = pass(&relay_a, &cx, &db, &publisher);
+        crate::assert_with_log!(!demoted.leader, "old leader idles", false, demoted.leader);
+
+        let broker = publisher.broker.lock();
+        let order = |key: &str| -> Vec<Vec<u8>> {
+            broker
+                .acked
+                .iter()
+                .filter(|(k, _, _)| k.as_deref() == Some(key))
+                .map(|(_, payload, _)| payload.clone())
+                .collect()
+        };
+        let a = order("a");
+        let expected_a = vec![b"a1".to_vec(), b"a2".to_vec(), b"a3".to_vec(), b"a4".to_vec()];
+        crate::assert_with_log!(a == expected_a, "a order", expected_a, a);
+        let b = order("b");
+        let expected_b = vec![b"b1".to_vec(), b"b2".to_vec()];
+        crate::assert_with_log!(b == expected_b, "b order", expected_b, b);
+        crate::test_complete!("concurrent_relays_preserve_per_key_order_under_one_leader");
+    }
+
+    #[test]
+    fn cleanup_removes_published_rows_after_retention_window() {
+        init_test("cleanup_removes_published_rows_after_retention_window");
+        let cx = Cx::for_testing();
+        let (now, config) = virtual_clock();
+        let retention = Duration::from_secs(60);
+        let outbox = Outbox::new(config.with_retention(retention)).unwrap();
+        let db = SharedDb::default();
+        let publisher = MockPublisher::default();
+        let relay = OutboxRelay::new(outbox.clone(), "relay-a");
+
+        enqueue_committed(&cx, &outbox, &db, &[("a", "a1"), ("b", "b1")]);
+        pass(&relay, &cx, &db, &publisher);
+        publisher.broker.lock().down = true;
+        enqueue_committed(&cx, &outbox, &db, &[("c", "c1")]);
+
+        advance(&now, Duration::from_secs(59));
+        let early = pass(&relay, &cx, &db, &publisher);
+        crate::assert_with_log!(early.cleaned == 0, "inside window", 0, early.cleaned);
+        let rows = db.lock().rows.len();
+        crate::assert_with_log!(rows == 3, "rows kept", 3, rows);
+
+        advance(&now, Duration::from_secs(2));
+        let late = pass(&relay, &cx, &db, &publisher);
+        crate::assert_with_log!(late.cleaned == 2, "published rows removed", 2, late.cleaned);
+        let remaining: Vec<Vec<u8>> = db
+            .lock()
+            .rows
+            .iter()
+            .map(|row| row.record.payload.clone())
+            .collect();
+        let expected = vec![b"c1".to_vec()];
+        crate::assert_with_log!(remaining == expected, "unpublished kept", expected, remaining);
+        let cleaned = relay.metrics().cleaned;
+        crate::assert_with_log!(cleaned == 2, "metric", 2, cleaned);
+        crate::test_complete!("cleanup_removes_published_rows_after_retention_window");
+    }
+
+    #[test]
+    fn broker_outage_accumulates_rows_and_grows_lag_without_loss() {
+        init_test("broker_outage_accumulates_rows_and_grows_lag_without_loss");
+        let cx = Cx::for_testing();
+        let (now, config) = virtual_clock();
+        let outbox = Outbox::new(config).unwrap();
+        let db = SharedDb::default();
+        let publisher = MockPublisher::default();
+        publisher.broker.lock().down = true;
+        let relay = OutboxRelay::new(outbox.clone(), "relay-a");
+
+        enqueue_committed(&cx, &outbox, &db, &[("a", "a1"), ("b", "b1")]);
+        pass(&relay, &cx, &db, &publisher);
+        advance(&now, Duration::from_secs(5));
+        enqueue_committed(&cx, &outbox, &db, &[("a", "a2")]);
+        pass(&relay, &cx, &db, &publisher);
+        let during = relay.metrics();
+        crate::assert_with_log!(
+            during.lag == Duration::from_secs(5),
+            "lag grows",
+            Duration::from_secs(5),
+            during.lag
+        );
+        crate::assert_with_log!(during.pending == 3, "pending", 3, during.pending);
+        // a2 is held behind a1, so only the key heads are attempted.
+        crate::assert_with_log!(
+            during.publish_failures == 4,
+            "failures",
+            4,
+            during.publish_failures
+        );
+        let last_error = relay.last_error();
+        crate::assert_with_log!(
+            last_error.as_deref() == Some("broker unavailable"),
+            "last error",
+            Some("broker unavailable"),
+            last_error
+        );
+
+        publisher.broker.lock().down = false;
+        advance(&now, Duration::from_secs(5));
+        let recovered = pass(&relay, &cx, &db, &publisher);
+        crate::assert_with_log!(recovered.published == 3, "drained", 3, recovered.published);
+        let after = relay.metrics();
+        crate::assert_with_log!(after.pending == 0, "no pending", 0, after.pending);
+        crate::assert_with_log!(after.published == 3, "published", 3, after.published);
+        let payloads = publisher.acked_payloads();
+        let expected = vec![b"a1".to_vec(), b"b1".to_vec(), b"a2".to_vec()];
+        crate::assert_with_log!(payloads == expected, "no loss", expected, payloads);
+        crate::test_complete!("broker_outage_accumulates_rows_and_grows_lag_without_loss");
+    }
+
+    #[test]
+    fn headers_round_trip_and_identifiers_are_validated() {
+        init_test("headers_round_trip_and_identifiers_are_validated");
+        let headers = vec![
+            ("content-type".to_string(), b"application/json".to_vec()),
+            ("trace".to_string(), Vec::new()),
+        ];
+        let decoded = decode_headers(&encode_headers(&headers));
+        crate::assert_with_log!(decoded.as_ref() == Some(&headers), "round trip", true, decoded);
+        let truncated = decode_headers(&[0, 5, b'a']);
+        crate::assert_with_log!(truncated.is_none(), "truncated", true, truncated);
+
+        let bad_table = Outbox::new(OutboxConfig::new("outbox; DROP TABLE users"));
+        crate::assert_with_log!(bad_table.is_err(), "table", true, bad_table.is_err());
+        let bad_key = Outbox::new(OutboxConfig::new("outbox").with_ordering_key_column("a.b"));
+        crate::assert_with_log!(bad_key.is_err(), "key column", true, bad_key.is_err());
+        let schema = OutboxConfig::new("app.outbox").postgres_schema();
+        let creates_leader = schema[2].contains("app.outbox_leader");
+        crate::assert_with_log!(creates_leader, "leader table", true, creates_leader);
+        crate::test_complete!("headers_round_trip_and_identifiers_are_validated");
+    }
+}
@@ -154,6 +154,12 @@ pub struct DbPoolConfig {
     /// Minimum delay between retry attempts per client in milliseconds.
     /// br-asupersync-mlojr9: Enforces per-client retry rate limiting.
     pub min_retry_delay_per_client_ms: u64,
+    /// Idle duration after which an async checkout pings the connection
+    /// even when `validate_on_checkout` is disabled.
+    pub health_check_after_idle: Duration,
+    /// Period of the async pool's background maintenance pass
+    /// ([`AsyncDbPool::run_maintenance`]).
+    pub maintenance_interval: Duration,
 }
 
 impl Default for DbPoolConfig {
@@ -173,6 +179,8 @@ impl Default for DbPoolConfig {
             // br-asupersync-mlojr9: Retry storm amplification DoS protection defaults
             max_retry_attempts_per_client: 5, // Max 5 retry attempts per client
             min_retry_delay_per_client_ms: 100, // Min 100ms between retries per client
+            health_check_after_idle: Duration::from_secs(30),
+            maintenance_interval: Duration::from_secs(30),
         }
     }
 }
@@ -282,6 +290,22 @@ impl DbPoolConfig {
         self
     }
 
+    /// Set the idle duration after which checkout pings the connection.
+    #[inline]
+    #[must_use]
+    pub fn health_check_after_idle(mut self, idle: Duration) -> Self {
+        self.health_check_after_idle = idle;
+        self
+    }
+
+    /// Set the background maintenance period.
+    #[inline]
+    #[must_use]
+    pub fn maintenance_interval(mut self, interval: Duration) -> Self {
+        self.maintenance_interval = interval;
+        self
+    }
+
     /// Returns the hard floor and ceiling used by advisory pool-sizing.
     #[inline]
     #[must_use]
@@ -317,6 +341,22 @@ impl<C> IdleConnection<C> {
     fn is_idle_too_long(&self, config: &DbPoolConfig, now: Time) -> bool {
         Duration::from_nanos(now.duration_since(self.last_used)) > config.idle_timeout
     }
+
+    fn is_due_for_health_check(&self, config: &DbPoolConfig, now: Time) -> bool {
+        Duration::from_nanos(now.duration_since(self.last_used)) > config.health_check_after_idle
+    }
+
+    fn needs_health_check(&self, config: &DbPoolConfig, now: Time) -> bool {
+        config.validate_on_checkout || self.is_due_for_health_check(config, now)
+    }
+}
+
+/// A connection dropped while its transaction was still open, parked until
+/// the async pool can roll it back and return it to the idle list.
+struct ParkedConnection<C> {
+    conn: C,
+    created_at: Time,
+    client_id: Option<String>,
 }
 
 struct PoolInner<C> {
@@ -333,6 +373,9 @@ struct PoolInner<C> {
     /// br-asupersync-mlojr9: Per-client retry attempt tracking.
     /// Maps client_id -> (current_attempts, last_retry_time).
     client_retry_state: HashMap<String, (u32, Time)>,
+    /// Async connections returned mid-transaction, awaiting rollback. They
+    /// still count toward `total`. Always empty for the synchronous pool.
+    pending_rollback: VecDeque<ParkedConnection<C>>,
 }
 
 struct AsyncPoolWaiter {
@@ -389,6 +432,75 @@ struct PoolStatCounters {
     total_validation_failures: AtomicU64,
     total_retry_limits_exceeded: AtomicU64,
     total_disconnect_failures: AtomicU64,
+    total_rollbacks_on_return: AtomicU64,
+    checkout_wait_buckets: [AtomicU64; CHECKOUT_WAIT_BUCKETS],
+}
+
+impl PoolStatCounters {
+    fn record_checkout_wait(&self, waited: Duration) {
+        let bucket = CheckoutWaitHistogram::bucket_for(waited);
+        self.checkout_wait_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn checkout_wait_histogram(&self) -> CheckoutWaitHistogram {
+        CheckoutWaitHistogram {
+            counts: std::array::from_fn(|bucket| {
+                self.checkout_wait_buckets[bucket].load(Ordering::Relaxed)
+            }),
+        }
+    }
+}
+
+const CHECKOUT_WAIT_BUCKETS: usize = 8;
+
+/// What one [`AsyncDbPool::maintain`] pass did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolMaintenanceReport {
+    /// Connections released mid-transaction that were rolled back and reused.
+    pub rolled_back: usize,
+    /// Idle connections pinged because they sat past the health-check threshold.
+    pub health_checked: usize,
+    /// Idle connections discarded as expired, stale, or failing their ping.
+    pub evicted: usize,
+    /// Connections opened to restore `min_idle`.
+    pub created: usize,
+}
+
+/// Distribution of how long async checkouts waited for a connection.
+///
+/// Bucket `i` counts checkouts that waited at most
+/// [`CheckoutWaitHistogram::UPPER_BOUNDS`]`[i]`; the last bucket counts
+/// everything slower.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckoutWaitHistogram {
+    /// Per-bucket checkout counts.
+    pub counts: [u64; CHECKOUT_WAIT_BUCKETS],
+}
+
+impl CheckoutWaitHistogram {
+    /// Inclusive upper bound of each bucket except the overflow bucket.
+    pub const UPPER_BOUNDS: [Duration; CHECKOUT_WAIT_BUCKETS - 1] = [
+        Duration::from_micros(100),
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+        Duration::from_millis(50),
+        Duration::from_millis(250),
+        Duration::from_secs(1),
+        Duration::from_secs(5),
+    ];
+
+    fn bucket_for(waited: Duration) -> usize {
+        Self::UPPER_BOUNDS
+            .iter()
+            .position(|bound| waited <= *bound)
+            .unwrap_or(CHECKOUT_WAIT_BUCKETS - 1)
+    }
+
+    /// Total checkouts recorded.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
 }
 
 impl fmt::Debug for PoolStatCounters {
@@ -419,6 +531,11 @@ impl fmt::Debug for PoolStatCounters {
                 "total_disconnect_failures",
                 &self.total_disconnect_failures.load(Ordering::Relaxed),
             )
+            .field(
+                "total_rollbacks_on_return",
+                &self.total_rollbacks_on_return.load(Ordering::Relaxed),
+            )
+            .field("checkout_wait", &self.checkout_wait_histogram())
             .finish()
     }
 }
@@ -458,6 +575,13 @@ pub struct DbPoolStats {
     /// sustained nonzero value signals the pool is saturated and acquisitions
     /// are queueing FIFO (rather than failing fast with `DbPoolError::Full`).
     pub pending_waiters: usize,
+    /// Connections returned mid-transaction and awaiting rollback.
+    pub pending_rollback: usize,
+    /// Connections rolled back and returned to the idle list after being
+    /// released with an open transaction.
+    pub total_rollbacks_on_return: u64,
+    /// How long async checkouts waited for a connection.
+    pub checkout_wait: CheckoutWaitHistogram,
 }
 
 impl DbPoolStats {
@@ -618,6 +742,7 @@ impl<M: ConnectionManager> DbPool<M> {
                 waiters: VecDeque::new(),
                 client_connections: HashMap::new(), // br-asupersync-qydi3j
                 client_retry_state: HashMap::new(), // br-asupersync-mlojr9
+                pending_rollback: VecDeque::new(),
             }),
             stats: PoolStatCounters::default(),
             live_max_size,
@@ -696,6 +821,9 @@ impl<M: ConnectionManager> DbPool<M> {
                 .load(Ordering::Relaxed),
             total_disconnect_failures: self.stats.total_disconnect_failures.load(Ordering::Relaxed),
             pending_waiters: inner.waiters.len(),
+            pending_rollback: inner.pending_rollback.len(),
+            total_rollbacks_on_return: self.stats.total_rollbacks_on_return.load(Ordering::Relaxed),
+            checkout_wait: self.stats.checkout_wait_histogram(),
         }
     }
 
@@ -1865,6 +1993,29 @@ pub trait AsyncConnectionManager: Send + Sync + 'static {
     /// Called when a connection is permanently removed from the pool.
     fn disconnect(&self, _conn: Self::Connection) {}
 
+    /// Returns `true` when a released connection still has an open
+    /// transaction that [`Self::rollback`] can clear.
+    ///
+    /// Such connections are not discarded on drop: the pool parks them and
+    /// rolls them back before they are handed to another caller. The default
+    /// reports no open transaction, leaving [`Self::release_check`] as the
+    /// only release-time gate.
+    fn needs_rollback(&self, _conn: &Self::Connection) -> bool {
+        false
+    }
+
+    /// Rolls back the open transaction on a released connection.
+    ///
+    /// Return `true` when the connection is clean and may be reused; `false`
+    /// discards it.
+    fn rollback(
+        &self,
+        _cx: &Cx,
+        _conn: &mut Self::Connection,
+    ) -> impl std::future::Future<Output = bool> + Send {
+        std::future::ready(false)
+    }
+
     /// Check if a connection has authentication state for a specific client.
     ///
     /// br-asupersync-80525g: Validation bypass fix - adds authentication state checking to async pool.
@@ -1986,6 +2137,7 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                 // br-asupersync-80525g: Validation bypass fix - add client tracking to async pool
                 client_connections: HashMap::new(),
                 client_retry_state: HashMap::new(),
+                pending_rollback: VecDeque::new(),
             }),
             stats: PoolStatCounters::default(),
             live_max_size,
@@ -2063,6 +2215,9 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                 .load(Ordering::Relaxed),
             total_disconnect_failures: self.stats.total_disconnect_failures.load(Ordering::Relaxed),
             pending_waiters: inner.waiters.len(),
+            pending_rollback: inner.pending_rollback.len(),
+            total_rollbacks_on_return: self.stats.total_rollbacks_on_return.load(Ordering::Relaxed),
+            checkout_wait: self.stats.checkout_wait_histogram(),
         }
     }
 
@@ -2205,7 +2360,13 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
             trace_async_pool_event(cx, "wait", "full", client_scope);
             return Err(DbPoolError::Full);
         }
-        let deadline = cx.now() + self.config.connection_timeout;
+        // The acquire budget is the tighter of the pool's connection timeout
+        // and the caller's ambient deadline, so a request handler never waits
+        // for a connection past the point its own budget expires.
+        let pool_deadline = cx.now() + self.config.connection_timeout;
+        let deadline = cx
+            .deadline()
+            .map_or(pool_deadline, |ambient| ambient.min(pool_deadline));
 
         let waiter = if let Some(waiter) = waiter_slot.as_ref() {
             Arc::clone(waiter)
@@ -2239,6 +2400,11 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                 trace_async_pool_event(cx, "wait", "ready", client_scope);
                 return Ok(());
             }
+            if !self.inner.lock().pending_rollback.is_empty() {
+                // Capacity is parked behind a rollback; return to the acquire
+                // loop so this caller performs it. The turn is not granted.
+                return Ok(());
+            }
 
             let remaining = Duration::from_nanos(deadline.duration_since(cx.now()));
             if remaining.is_zero() {
@@ -2273,9 +2439,26 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
     }
 
     /// Acquire a connection from the pool.
+    ///
+    /// Waiters are served in FIFO order. Waiting is bounded by the tighter of
+    /// [`DbPoolConfig::connection_timeout`] and the caller's ambient
+    /// deadline; exhausting it returns [`DbPoolError::AcquireTimeout`].
     pub async fn get(
         &self,
         cx: &Cx,
+    ) -> Result<AsyncPooledConnection<'_, M>, DbPoolError<M::Error>> {
+        let started = cx.now();
+        let acquired = self.acquire_anonymous(cx).await;
+        if acquired.is_ok() {
+            self.stats
+                .record_checkout_wait(Duration::from_nanos(cx.now().duration_since(started)));
+        }
+        acquired
+    }
+
+    async fn acquire_anonymous(
+        &self,
+        cx: &Cx,
     ) -> Result<AsyncPooledConnection<'_, M>, DbPoolError<M::Error>> {
         trace_async_pool_event(cx, "acquire", "start", "anonymous");
         let mut waiter_guard = AsyncWaiterGuard {
@@ -2288,6 +2471,9 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                 trace_async_pool_event(cx, "acquire", "cancelled", "anonymous");
                 return Err(DbPoolError::Timeout);
             }
+            // Connections released mid-transaction are rolled back here,
+            // before they become visible on the idle list again.
+            self.reset_parked_connections(cx).await;
 
             let step = {
                 let mut inner = self.inner.lock();
@@ -2403,7 +2589,7 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                     continue;
                 }
 
-                if self.config.validate_on_checkout {
+                if idle.needs_health_check(&self.config, now) {
                     let mut guard = AsyncValidationGuard {
                         pool: self,
                         conn: Some(idle.conn),
@@ -2447,11 +2633,25 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
         &self,
         cx: &Cx,
         client_id: &str,
+    ) -> Result<AsyncPooledConnection<'_, M>, DbPoolError<M::Error>> {
+        let started = cx.now();
+        let acquired = self.acquire_for_client(cx, client_id).await;
+        if acquired.is_ok() {
+            self.stats
+                .record_checkout_wait(Duration::from_nanos(cx.now().duration_since(started)));
+        }
+        acquired
+    }
+
+    async fn acquire_for_client(
+        &self,
+        cx: &Cx,
+        client_id: &str,
     ) -> Result<AsyncPooledConnection<'_, M>, DbPoolError<M::Error>> {
         trace_async_pool_event(cx, "acquire", "start", "client");
-        // If client quotas are disabled, delegate to regular get()
+        // If client quotas are disabled, delegate to the anonymous path.
         if !self.config.enforce_client_quotas {
-            return self.get(cx).await.map(|mut conn| {
+            return self.acquire_anonymous(cx).await.map(|mut conn| {
                 conn.client_id = Some(client_id.to_string());
                 conn
             });
@@ -2468,6 +2668,9 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                 trace_async_pool_event(cx, "acquire", "cancelled", "client");
                 return Err(DbPoolError::Timeout);
             }
+            // Connections released mid-transaction are rolled back here,
+            // before they become visible on the idle list again.
+            self.reset_parked_connections(cx).await;
 
             let step = {
                 let mut inner = self.inner.lock();
@@ -2662,7 +2865,7 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
                     continue;
                 }
 
-                if self.config.validate_on_checkout {
+                if idle.needs_health_check(&self.config, now) {
                     let mut guard = AsyncValidationGuard {
                         pool: self,
                         conn: Some(idle.conn),
@@ -2861,6 +3064,158 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
         }
     }
 
+    /// Roll back every connection parked by a mid-transaction release and
+    /// return the clean ones to the idle list.
+    async fn reset_parked_connections(&self, cx: &Cx) -> usize {
+        let mut reset = 0;
+        loop {
+            let Some(parked) = self.inner.lock().pending_rollback.pop_front() else {
+                return reset;
+            };
+            // The guard discards the connection (and frees its `total`
+            // slot) if this future is dropped mid-rollback.
+            let mut guard = AsyncValidationGuard {
+                pool: self,
+                conn: Some(parked.conn),
+            };
+            let conn = guard.conn.as_mut().expect("parked connection present");
+            let clean =
+                self.manager.rollback(cx, conn).await && self.manager.release_check(conn);
+            if clean {
+                let conn = guard.conn.take().expect("parked connection present");
+                self.stats
+                    .total_rollbacks_on_return
+                    .fetch_add(1, Ordering::Relaxed);
+                trace_async_pool_event(cx, "rollback_on_return", "reused", "pool");
+                self.return_connection(conn, parked.created_at, parked.client_id);
+                reset += 1;
+            } else {
+                trace_async_pool_event(cx, "rollback_on_return", "discarded", "pool");
+                drop(guard);
+            }
+        }
+    }
+
+    /// Run one maintenance pass over the idle connections.
+    ///
+    /// Rolls back connections released mid-transaction, evicts connections
+    /// past `max_lifetime` or `idle_timeout`, pings connections idle longer
+    /// than `health_check_after_idle` (discarding those that fail), and
+    /// tops the pool back up to `min_idle`.
+    pub async fn maintain(&self, cx: &Cx) -> PoolMaintenanceReport {
+        let mut report = PoolMaintenanceReport {
+            rolled_back: self.reset_parked_connections(cx).await,
+            ..PoolMaintenanceReport::default()
+        };
+
+        let now = cx.now();
+        let candidates: Vec<IdleConnection<M::Connection>> = {
+            let mut inner = self.inner.lock();
+            if inner.closed {
+                return report;
+            }
+            inner.idle.drain(..).collect()
+        };
+
+        for idle in candidates {
+            if idle.is_expired(&self.config, now) || idle.is_idle_too_long(&self.config, now) {
+                report.evicted += 1;
+                drop(AsyncValidationGuard {
+                    pool: self,
+                    conn: Some(idle.conn),
+                });
+                continue;
+            }
+
+            if idle.is_due_for_health_check(&self.config, now) {
+                let mut guard = AsyncValidationGuard {
+                    pool: self,
+                    conn: Some(idle.conn),
+                };
+                let valid = self
+                    .manager
+                    .is_valid(cx, guard.conn.as_mut().expect("idle connection present"))
+                    .await;
+                report.health_checked += 1;
+                if !valid {
+                    self.stats
+                        .total_validation_failures
+                        .fetch_add(1, Ordering::Relaxed);
+                    report.evicted += 1;
+                    continue;
+                }
+                let conn = guard.conn.take().expect("idle connection present");
+                self.requeue_idle(IdleConnection {
+                    conn,
+                    last_used: cx.now(),
+                    ..idle
+                });
+                continue;
+            }
+
+            self.requeue_idle(idle);
+        }
+
+        while self.reserve_min_idle_slot() {
+            let mut creation_guard = AsyncCreationGuard {
+                pool: self,
+                disarmed: false,
+            };
+            match self.manager.connect(cx).await {
+                Outcome::Ok(conn) => {
+                    creation_guard.disarmed = true;
+                    self.stats.total_creates.fetch_add(1, Ordering::Relaxed);
+                    self.return_connection(conn, cx.now(), None);
+                    report.created += 1;
+                }
+                Outcome::Err(_) | Outcome::Cancelled(_) | Outcome::Panicked(_) => break,
+            }
+        }
+
+        trace_async_pool_event(cx, "maintenance", "complete", "pool");
+        report
+    }
+
+    /// Run [`maintain`](Self::maintain) every
+    /// [`DbPoolConfig::maintenance_interval`] until the pool closes or `cx`
+    /// is cancelled.
+    pub async fn run_maintenance(&self, cx: &Cx) {
+        loop {
+            crate::time::sleep(cx.now(), self.config.maintenance_interval).await;
+            if cx.checkpoint().is_err() || self.is_closed() {
+                return;
+            }
+            let _ = self.maintain(cx).await;
+        }
+    }
+
+    fn requeue_idle(&self, idle: IdleConnection<M::Connection>) {
+        let mut inner = self.inner.lock();
+        if inner.closed {
+            inner.total = inner.total.saturating_sub(1);
+            drop(inner);
+            self.stats.total_discards.fetch_add(1, Ordering::Relaxed);
+            self.manager.disconnect(idle.conn);
+            return;
+        }
+        inner.idle.push_back(idle);
+        self.wake_next_async_pool_waiter_locked(&mut inner);
+    }
+
+    fn reserve_min_idle_slot(&self) -> bool {
+        let mut inner = self.inner.lock();
+        if inner.closed
+            || inner.idle.len() >= self.config.min_idle
+            || inner.total >= self.effective_max_size()
+            // Queued callers get first claim on free capacity.
+            || !inner.waiters.is_empty()
+        {
+            return false;
+        }
+        inner.total += 1;
+        true
+    }
+
     fn finish_async_checkout(
         &self,
         conn: M::Connection,
@@ -2953,7 +3308,8 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
     pub fn close(&self) {
         let mut inner = self.inner.lock();
         inner.closed = true;
-        let idle: Vec<_> = inner.idle.drain(..).collect();
+        let mut idle: Vec<_> = inner.idle.drain(..).map(|entry| entry.conn).collect();
+        idle.extend(inner.pending_rollback.drain(..).map(|parked| parked.conn));
         let drained = idle.len();
         inner.total = inner.total.saturating_sub(drained);
         self.wake_all_async_pool_waiters_locked(&mut inner);
@@ -2965,8 +3321,8 @@ impl<M: AsyncConnectionManager> AsyncDbPool<M> {
         drop(inner);
         // br-asupersync-80525g: Use safe disconnect to prevent resource leaks during async pool close
         let mut failed_disconnects = 0;
-        for entry in idle {
-            if !self.safe_disconnect(entry.conn) {
+        for conn in idle {
+            if !self.safe_disconnect(conn) {
                 failed_disconnects += 1;
             }
         }
@@ -3076,6 +3432,17 @@ impl<M: AsyncConnectionManager> AsyncPooledConnection<'_, M> {
         drop(self);
     }
 
+    /// Return the connection to the pool, rolling back any transaction the
+    /// caller left open before it becomes available to other callers.
+    ///
+    /// Dropping the guard parks such a connection until the next acquire or
+    /// maintenance pass rolls it back; `release` does that work inline.
+    pub async fn release(self, cx: &Cx) {
+        let pool = self.pool;
+        drop(self);
+        let _ = pool.reset_parked_connections(cx).await;
+    }
+
     /// Discard this connection instead of returning it.
     pub fn discard(mut self) {
         if let Some(conn) = self.conn.take() {
@@ -3119,6 +3486,26 @@ impl<M: AsyncConnectionManager> Drop for AsyncPooledConnection<'_, M> {
                 }
             }
 
+            // An open transaction is recoverable: park the connection so the
+            // pool can roll it back asynchronously instead of reconnecting.
+            if self.pool.manager.needs_rollback(&conn) {
+                let mut inner = self.pool.inner.lock();
+                if !inner.closed {
+                    inner.pending_rollback.push_back(ParkedConnection {
+                        conn,
+                        created_at: self.created_at,
+                        client_id: self.client_id.clone(),
+                    });
+                    // Nudge the head waiter (without granting it a turn) so it
+                    // performs the rollback that frees this capacity.
+                    if let Some(waiter) = inner.waiters.front() {
+                        waiter.notify.notify_one();
+                    }
+                    return;
+                }
+                drop(inner);
+            }
+
             // br-asupersync-5bv5sr: gate on the manager's release-time
             // health check; discard rather than return-to-pool when the
             // backend reports the connection is in a state that would
@@ -4492,6 +4879,256 @@ mod tests {
         crate::test_complete!("async_pool_dropped_parked_waiter_does_not_wedge_queue");
     }
 
+    #[test]
+    fn async_get_respects_ambient_cx_deadline() {
+        init_test("async_get_respects_ambient_cx_deadline");
+        let pool = AsyncDbPool::new(
+            AsyncTestManager::new(),
+            DbPoolConfig::with_max_size(1)
+                .validate_on_checkout(false)
+                .connection_timeout(Duration::from_secs(5)),
+        );
+        let holder_cx = Cx::for_testing();
+        let holder = block_on(pool.get(&holder_cx)).expect("holder acquires the only slot");
+
+        let deadline = holder_cx.now() + Duration::from_millis(80);
+        let waiter_cx = Cx::for_testing_with_budget(Budget::INFINITE.with_deadline(deadline));
+        let started = Instant::now();
+        let outcome = block_on(pool.get(&waiter_cx));
+        let elapsed = started.elapsed();
+
+        assert!(
+            matches!(
+                outcome,
+                Err(DbPoolError::AcquireTimeout | DbPoolError::Timeout)
+            ),
+            "waiter must give up once its own deadline passes"
+        );
+        assert!(
+            elapsed < Duration::from_secs(2),
+            "ambient deadline must bound the wait well below connection_timeout, observed {elapsed:?}"
+        );
+        assert_eq!(pool.stats().pending_waiters, 0);
+
+        holder.return_to_pool();
+        crate::test_complete!("async_get_respects_ambient_cx_deadline");
+    }
+
+    #[test]
+    fn async_checkout_health_checks_connections_idle_past_threshold() {
+        init_test("async_checkout_health_checks_connections_idle_past_threshold");
+        let manager = AsyncTestManager::new();
+        let valid = Arc::clone(&manager.valid);
+        let pool = AsyncDbPool::new(
+            manager,
+            DbPoolConfig::with_max_size(2)
+                .validate_on_checkout(false)
+                .health_check_after_idle(Duration::from_millis(20)),
+        );
+        let cx = Cx::for_testing();
+
+        let first = block_on(pool.get(&cx)).expect("first checkout");
+        let first_id = first.id;
+        first.return_to_pool();
+
+        // Freshly returned: below the idle threshold, so no ping.
+        let again = block_on(pool.get(&cx)).expect("reuse without ping");
+        assert_eq!(again.id, first_id);
+        again.return_to_pool();
+
+        // The server drops the connection while it sits idle.
+        valid.store(false, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(30));
+
+        let replacement = block_on(pool.get(&cx)).expect("replacement checkout");
+        assert_ne!(
+            replacement.id, first_id,
+            "a connection failing its idle ping must be recycled"
+        );
+        let stats = pool.stats();
+        assert_eq!(stats.total_validation_failures, 1);
+        assert_eq!(stats.total_creates, 2);
+        assert_eq!(stats.total, 1);
+        drop(replacement);
+        crate::test_complete!("async_checkout_health_checks_connections_idle_past_threshold");
+    }
+
+    #[test]
+    fn async_maintenance_recycles_expired_connections_under_lab_runtime() {
+        init_test("async_maintenance_recycles_expired_connections_under_lab_runtime");
+        let config = TestConfig::new()
+            .with_seed(0xD8A5_E659)
+            .with_max_steps(20_000);
+        let mut runtime = LabRuntimeTarget::create_runtime(config);
+        let pool = Arc::new(AsyncDbPool::new(
+            AsyncTestManager::new(),
+            DbPoolConfig::with_max_size(2)
+                .min_idle(1)
+                .validate_on_checkout(false)
+                .max_lifetime(Duration::from_secs(60))
+                .idle_timeout(Duration::from_secs(3600))
+                .health_check_after_idle(Duration::from_secs(3600)),
+        ));
+
+        let (first_id, early, late, replacement_id, stats) =
+            LabRuntimeTarget::block_on(&mut runtime, async move {
+                let cx = Cx::current().expect("lab runtime should install a current Cx");
+                let lease = pool.get(&cx).await.expect("initial checkout");
+                let first_id = lease.id;
+                lease.return_to_pool();
+
+                crate::time::sleep(cx.now(), Duration::from_secs(30)).await;
+                let early = pool.maintain(&cx).await;
+
+                crate::time::sleep(cx.now(), Duration::from_secs(31)).await;
+                let late = pool.maintain(&cx).await;
+
+                let lease = pool.get(&cx).await.expect("checkout after recycling");
+                let replacement_id = lease.id;
+                lease.return_to_pool();
+                (first_id, early, late, replacement_id, pool.stats())
+            });
+
+        assert_eq!(early, PoolMaintenanceReport::default());
+        assert_eq!(late.evicted, 1, "connection past max_lifetime is evicted");
+        assert_eq!(late.created, 1, "maintenance restores min_idle");
+        assert_ne!(replacement_id, first_id);
+        assert_eq!(stats.total, 1);
+        assert_eq!(stats.total_creates, 2);
+        assert!(runtime.is_quiescent());
+        crate::test_complete!("async_maintenance_recycles_expired_connections_under_lab_runtime");
+    }
+
+    struct TxConnection {
+        id: usize,
+        in_transaction: bool,
+    }
+
+    struct TxTestManager {
+        next_id: AtomicUsize,
+        rollbacks: AtomicUsize,
+        rollback_succeeds: AtomicBool,
+    }
+
+    impl TxTestManager {
+        fn new(rollback_succeeds: bool) -> Self {
+            Self {
+                next_id: AtomicUsize::new(1),
+                rollbacks: AtomicUsize::new(0),
+                rollback_succeeds: AtomicBool::new(rollback_succeeds),
+            }
+        }
+    }
+
+    impl AsyncConnectionManager for TxTestManager {
+        type Connection = TxConnection;
+        type Error = TestError;
+
+        async fn connect(&self, _cx: &Cx) -> Outcome<Self::Connection, Self::Error> {
+            Outcome::Ok(TxConnection {
+                id: self.next_id.fetch_add(1, Ordering::SeqCst),
+                in_transaction: false,
+            })
+        }
+
+        async fn is_valid(&self, _cx: &Cx, _conn: &mut Self::Connection) -> bool {
+            true
+        }
+
+        fn release_check(&self, conn: &mut Self::Connection) -> bool {
+            !conn.in_transaction
+        }
+
+        fn needs_rollback(&self, conn: &Self::Connection) -> bool {
+            conn.in_transaction
+        }
+
+        async fn rollback(&self, _cx: &Cx, conn: &mut Self::Connection) -> bool {
+            self.rollbacks.fetch_add(1, Ordering::SeqCst);
+            if self.rollback_succeeds.load(Ordering::SeqCst) {
+                conn.in_transaction = false;
+            }
+            !conn.in_transaction
+        }
+    }
+
+    #[test]
+    fn async_pool_rolls_back_open_transaction_before_reuse() {
+        init_test("async_pool_rolls_back_open_transaction_before_reuse");
+        let pool = AsyncDbPool::new(
+            TxTestManager::new(true),
+            DbPoolConfig::with_max_size(1).validate_on_checkout(false),
+        );
+        let cx = Cx::for_testing();
+
+        let mut lease = block_on(pool.get(&cx)).expect("first checkout");
+        let first_id = lease.id;
+        lease.in_transaction = true;
+        drop(lease);
+
+        let stats = pool.stats();
+        assert_eq!(stats.pending_rollback, 1, "open transaction parks the connection");
+        assert_eq!(stats.idle, 0);
+        assert_eq!(stats.total, 1);
+
+        let lease = block_on(pool.get(&cx)).expect("checkout after rollback");
+        assert_eq!(lease.id, first_id, "rolled-back connection is reused");
+        assert!(!lease.in_transaction);
+        block_on(lease.release(&cx));
+
+        let stats = pool.stats();
+        assert_eq!(stats.total_rollbacks_on_return, 1);
+        assert_eq!(stats.total_creates, 1);
+        assert_eq!(stats.pending_rollback, 0);
+        assert_eq!(stats.idle, 1);
+        assert_eq!(stats.checkout_wait.total(), 2);
+        crate::test_complete!("async_pool_rolls_back_open_transaction_before_reuse");
+    }
+
+    #[test]
+    fn async_pool_discards_connection_when_rollback_fails() {
+        init_test("async_pool_discards_connection_when_rollback_fails");
+        let pool = AsyncDbPool::new(
+            TxTestManager::new(false),
+            DbPoolConfig::with_max_size(1).validate_on_checkout(false),
+        );
+        let cx = Cx::for_testing();
+
+        let mut lease = block_on(pool.get(&cx)).expect("first checkout");
+        let first_id = lease.id;
+        lease.in_transaction = true;
+        block_on(lease.release(&cx));
+
+        let stats = pool.stats();
+        assert_eq!(stats.total, 0, "failed rollback frees the slot");
+        assert_eq!(stats.total_discards, 1);
+        assert_eq!(stats.total_rollbacks_on_return, 0);
+
+        let lease = block_on(pool.get(&cx)).expect("fresh connection");
+        assert_ne!(lease.id, first_id);
+        assert_eq!(pool.manager.rollbacks.load(Ordering::SeqCst), 1);
+        crate::test_complete!("async_pool_discards_connection_when_rollback_fails");
+    }
+
+    #[test]
+    fn checkout_wait_histogram_buckets_by_upper_bound() {
+        init_test("checkout_wait_histogram_buckets_by_upper_bound");
+        assert_eq!(CheckoutWaitHistogram::bucket_for(Duration::ZERO), 0);
+        assert_eq!(
+            CheckoutWaitHistogram::bucket_for(Duration::from_millis(1)),
+            1
+        );
+        assert_eq!(
+            CheckoutWaitHistogram::bucket_for(Duration::from_millis(30)),
+            3
+        );
+        assert_eq!(
+            CheckoutWaitHistogram::bucket_for(Duration::from_secs(60)),
+            CHECKOUT_WAIT_BUCKETS - 1
+        );
+        crate::test_complete!("checkout_wait_histogram_buckets_by_upper_bound");
+    }
+
     #[test]
     fn pool_with_manager() {
         init_test("pool_with_manager");
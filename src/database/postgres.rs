@@ -8428,18 +8428,22 @@ impl crate::database::pool::AsyncConnectionManager for PgConnectionManager {
         PgConnection::connect_with_options(cx, self.options.clone()).await
     }
 
-    async fn is_valid(&self, _cx: &Cx, conn: &mut Self::Connection) -> bool {
+    async fn is_valid(&self, cx: &Cx, conn: &mut Self::Connection) -> bool {
         // A connection is valid for reuse iff it is open, not in a
-        // transaction, not flagged for discard, and not unhealthy. The
-        // is_valid hook may run async queries (e.g. SELECT 1) but for
-        // the cheap check here we use the locally-tracked flags; the
-        // pool's separate health-check path is responsible for
-        // periodic SELECT 1 probes.
-        !conn.inner.closed
-            && !conn.in_transaction()
-            && !conn.needs_discard()
-            && !conn.is_unhealthy()
-            && conn.transport_matches_ssl_mode(self.options.ssl_mode)
+        // transaction, not flagged for discard, not unhealthy, and the
+        // server still answers. The locally-tracked flags are checked
+        // first so an obviously unusable connection never costs a round
+        // trip; the `SELECT 1` probe catches sockets the server (or a
+        // middlebox) closed while the connection sat idle.
+        if conn.inner.closed
+            || conn.in_transaction()
+            || conn.needs_discard()
+            || conn.is_unhealthy()
+            || !conn.transport_matches_ssl_mode(self.options.ssl_mode)
+        {
+            return false;
+        }
+        matches!(conn.execute_unchecked(cx, "SELECT 1").await, Outcome::Ok(_))
     }
 
     /// br-asupersync-a1x452 + br-asupersync-t4wfzb: refuse to recycle
@@ -8478,6 +8482,23 @@ impl crate::database::pool::AsyncConnectionManager for PgConnectionManager {
         true
     }
 
+    /// A connection left inside a plain transaction is recoverable with a
+    /// `ROLLBACK`. Connections tagged `needs_discard()` are excluded: that
+    /// flag also covers session-level state (`SET ROLE`, dropped
+    /// `PgTransaction` guards) which a rollback cannot be trusted to undo.
+    fn needs_rollback(&self, conn: &Self::Connection) -> bool {
+        !conn.inner.closed
+            && !conn.needs_discard()
+            && !conn.is_unhealthy()
+            && conn.transport_matches_ssl_mode(self.options.ssl_mode)
+            && conn.in_transaction()
+    }
+
+    async fn rollback(&self, cx: &Cx, conn: &mut Self::Connection) -> bool {
+        conn.inner.needs_rollback = true;
+        conn.clear_orphaned_transaction(cx).await.is_ok() && !conn.in_transaction()
+    }
+
     fn disconnect(&self, _conn: Self::Connection) {
         // PgConnectionInner::Drop handles the wire-level close
         // (br-asupersync-1wygbs sends Terminate before TCP shutdown).
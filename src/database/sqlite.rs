@@ -38,7 +38,7 @@ use crate::cx::Cx;
 use crate::database::transaction::trace_database_transaction;
 use crate::obligation::graded::{ObligationToken, TransactionKind};
 use crate::runtime::blocking_pool::{BlockingPool, BlockingPoolHandle};
-use crate::time::{sleep, wall_now};
+use crate::time::{Sleep, sleep, wall_now};
 use crate::types::{CancelReason, Outcome};
 use parking_lot::Mutex;
 use std::collections::BTreeMap;
@@ -51,6 +51,14 @@ use std::sync::{Arc, OnceLock};
 use std::task::Poll;
 use std::time::Duration;
 
+pub mod maintenance;
+
+pub use maintenance::{
+    QuietWindow, SqliteBusyRetry, SqliteBusyRetryMetrics, WalCheckpointMode, WalCheckpointOutcome,
+    WalCheckpointTrigger, WalMaintainer, WalMaintenanceConfig, WalMaintenanceMetrics,
+    WalMaintenancePass, WalStatus,
+};
+
 /// Global blocking pool for SQLite operations.
 ///
 /// Keep the pool itself alive for the process lifetime. Storing only
@@ -89,10 +97,12 @@ async fn sqlite_wait_retry_delay(cx: &Cx, delay: Duration) -> Result<(), CancelR
         return cx.checkpoint().map_err(|_| sqlite_cancelled_reason(cx));
     }
 
-    let now = cx
-        .timer_driver()
-        .map_or_else(wall_now, |driver| driver.now());
-    let mut sleeper = sleep(now, delay);
+    // Bind to the Cx timer driver so backoff follows the caller's clock
+    // (virtual time under the lab) even when polled outside a runtime.
+    let mut sleeper = match cx.timer_driver() {
+        Some(driver) => Sleep::with_timer_driver(driver.now() + delay, driver),
+        None => sleep(wall_now(), delay),
+    };
     poll_fn(|task_cx| {
         if cx.checkpoint().is_err() {
             return Poll::Ready(Err(sqlite_cancelled_reason(cx)));
@@ -2484,6 +2494,13 @@ impl SqliteConnection {
         self.inner.lock().conn.is_some()
     }
 
+    /// Returns true if a transaction is open or awaiting its drop-time
+    /// rollback.
+    #[must_use]
+    pub fn in_transaction(&self) -> bool {
+        *self.transaction_state.lock() != TransactionState::Autocommit
+    }
+
     /// Execute WAL checkpoint with retry logic and verification
     fn execute_wal_checkpoint_with_retry(
         &self,
@@ -2678,6 +2695,60 @@ impl SqliteConnection {
     }
 }
 
+/// [`AsyncConnectionManager`](crate::database::pool::AsyncConnectionManager)
+/// that opens SQLite connections to a fixed database path, so SQLite shares
+/// the same [`AsyncDbPool`](crate::database::pool::AsyncDbPool) as the
+/// PostgreSQL and MySQL clients.
+#[derive(Debug, Clone)]
+pub struct SqliteConnectionManager {
+    path: PathBuf,
+}
+
+impl SqliteConnectionManager {
+    /// Create a manager that opens connections to the database at `path`.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the database path connections are opened against.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl crate::database::pool::AsyncConnectionManager for SqliteConnectionManager {
+    type Connection = SqliteConnection;
+    type Error = SqliteError;
+
+    async fn connect(&self, cx: &Cx) -> Outcome<Self::Connection, Self::Error> {
+        SqliteConnection::open(cx, &self.path).await
+    }
+
+    async fn is_valid(&self, cx: &Cx, conn: &mut Self::Connection) -> bool {
+        if !conn.is_open() || conn.in_transaction() {
+            return false;
+        }
+        matches!(conn.execute_batch(cx, "SELECT 1").await, Outcome::Ok(()))
+    }
+
+    fn release_check(&self, conn: &mut Self::Connection) -> bool {
+        conn.is_open() && !conn.in_transaction()
+    }
+
+    /// A dropped [`SqliteTransaction`] leaves the connection awaiting its
+    /// rollback; the pool performs it before the next checkout.
+    fn needs_rollback(&self, conn: &Self::Connection) -> bool {
+        conn.is_open() && *conn.transaction_state.lock() == TransactionState::NeedsRollback
+    }
+
+    async fn rollback(&self, cx: &Cx, conn: &mut Self::Connection) -> bool {
+        matches!(conn.drain_orphaned_transaction(cx).await, Outcome::Ok(()))
+            && !conn.in_transaction()
+    }
+}
+
 /// A SQLite transaction.
 ///
 /// The transaction will be rolled back on drop if not committed.
@@ -0,0 +1,1236 @@
+//! Managed WAL checkpointing and async busy retry for SQLite.
+//!
+//! SQLite's built-in WAL maintenance is synchronous: `wal_autocheckpoint`
+//! runs on whichever writer happens to cross the page threshold, and the
+//! busy handler blocks a pool thread with its own sleep loop. This module
+//! moves both onto the runtime:
+//!
+//! - [`WalMaintainer`] is a maintenance task the application spawns in the
+//!   region that owns the connection. Each pass reads the WAL file size and
+//!   runs a `PASSIVE` checkpoint once it crosses the configured threshold,
+//!   escalating to `TRUNCATE` inside configured quiet windows or on explicit
+//!   request. Every checkpoint emits a structured outcome event (frames in
+//!   the log, frames checkpointed, whether readers blocked it), and a
+//!   checkpoint that stays blocked longer than the warning threshold emits a
+//!   long-reader warning.
+//! - [`SqliteBusyRetry`] replaces the C busy handler: `SQLITE_BUSY` results
+//!   are retried with exponential backoff slept on the [`Cx`] clock, retries
+//!   stop early when the next backoff would overrun the ambient deadline,
+//!   and attempt counts are kept in [`SqliteBusyRetryMetrics`]. Pair it with
+//!   [`SqliteConnection::set_busy_timeout`] set to zero so the two do not
+//!   stack.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::database::{QuietWindow, WalMaintainer, WalMaintenanceConfig};
+//!
+//! // PASSIVE above 8 MiB, TRUNCATE between 03:00 and 04:00 UTC.
+//! let maintainer = WalMaintainer::new(
+//!     WalMaintenanceConfig::new()
+//!         .with_size_threshold(8 * 1024 * 1024)
+//!         .with_quiet_window(QuietWindow::daily(
+//!             Duration::from_secs(3 * 3600),
+//!             Duration::from_secs(3600),
+//!         )),
+//! );
+//! // Runs until the owning region is cancelled.
+//! scope.spawn(cx, |cx| async move { maintainer.run(&cx, &conn).await });
+//! ```
+
+use super::{SqliteConnection, SqliteError, sqlite_cancelled_reason, sqlite_wait_retry_delay};
+use crate::cx::Cx;
+use crate::database::transaction::RetryPolicy;
+use crate::types::{Outcome, Time};
+use parking_lot::Mutex;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+macro_rules! try_outcome {
+    ($expr:expr) => {
+        match $expr {
+            Outcome::Ok(value) => value,
+            Outcome::Err(err) => return Outcome::Err(err),
+            Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+            Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+        }
+    };
+}
+
+fn wal_file_len(conn: &rusqlite::Connection) -> Result<u64, SqliteError> {
+    let Some(path) = conn.path().filter(|path| !path.is_empty()) else {
+        return Ok(0);
+    };
+    match std::fs::metadata(format!("{path}-wal")) {
+        Ok(meta) => Ok(meta.len()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(SqliteError::Sqlite(format!("failed to read WAL file size: {err}"))),
+    }
+}
+
+fn frames(value: i64) -> u64 {
+    // SQLite reports -1 for both columns when the database is not in WAL mode.
+    u64::try_from(value).unwrap_or(0)
+}
+
+// ─── Checkpoint primitives ───────────────────────────────────────────────────
+
+/// `PRAGMA wal_checkpoint` mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalCheckpointMode {
+    /// Checkpoint as many frames as possible without waiting on readers or
+    /// writers.
+    Passive,
+    /// Wait for writers, then checkpoint every frame.
+    Full,
+    /// Like [`Full`](Self::Full), then wait for readers so the next writer
+    /// restarts the log from the beginning.
+    Restart,
+    /// Like [`Restart`](Self::Restart), then truncate the WAL file to zero
+    /// bytes.
+    Truncate,
+}
+
+impl WalCheckpointMode {
+    /// The SQL keyword for this mode.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Passive => "PASSIVE",
+            Self::Full => "FULL",
+            Self::Restart => "RESTART",
+            Self::Truncate => "TRUNCATE",
+        }
+    }
+}
+
+impl fmt::Display for WalCheckpointMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of one WAL checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalCheckpointOutcome {
+    /// Mode the checkpoint ran in.
+    pub mode: WalCheckpointMode,
+    /// SQLite's `busy` column: a blocking mode could not finish because of
+    /// concurrent readers or writers.
+    pub busy: bool,
+    /// Frames in the WAL.
+    pub log_frames: u64,
+    /// Frames copied back into the database file.
+    pub checkpointed_frames: u64,
+    /// WAL file size in bytes after the checkpoint.
+    pub wal_bytes: u64,
+}
+
+impl WalCheckpointOutcome {
+    /// Frames left in the WAL because a reader still needs them.
+    #[must_use]
+    pub const fn pending_frames(&self) -> u64 {
+        self.log_frames.saturating_sub(self.checkpointed_frames)
+    }
+
+    /// Whether readers kept the checkpoint from completing.
+    #[must_use]
+    pub const fn blocked_by_readers(&self) -> bool {
+        self.busy || self.pending_frames() > 0
+    }
+}
+
+/// WAL size and change counter of a connection's main database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalStatus {
+    /// WAL file size in bytes (zero for in-memory databases).
+    pub wal_bytes: u64,
+    /// `PRAGMA data_version`; changes whenever another connection commits.
+    pub data_version: i64,
+}
+
+impl SqliteConnection {
+    /// Runs `PRAGMA wal_checkpoint(<mode>)` and reports the outcome.
+    ///
+    /// Blocking modes wait on the connection's busy timeout before reporting
+    /// [`busy`](WalCheckpointOutcome::busy).
+    pub async fn wal_checkpoint(
+        &self,
+        cx: &Cx,
+        mode: WalCheckpointMode,
+    ) -> Outcome<WalCheckpointOutcome, SqliteError> {
+        if cx.checkpoint().is_err() {
+            return Outcome::Cancelled(sqlite_cancelled_reason(cx));
+        }
+        self.run_connection_op(cx, "sqlite wal_checkpoint", move |conn| {
+            let sql = format!("PRAGMA wal_checkpoint({mode})");
+            let (busy, log, checkpointed) = conn
+                .query_row(&sql, [], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                })
+                .map_err(|e| SqliteError::Sqlite(e.to_string()))?;
+            Ok(WalCheckpointOutcome {
+                mode,
+                busy: busy != 0,
+                log_frames: frames(log),
+                checkpointed_frames: frames(checkpointed),
+                wal_bytes: wal_file_len(conn)?,
+            })
+        })
+        .await
+    }
+
+    /// Reads the WAL file size and `data_version` of the main database.
+    pub async fn wal_status(&self, cx: &Cx) -> Outcome<WalStatus, SqliteError> {
+        if cx.checkpoint().is_err() {
+            return Outcome::Cancelled(sqlite_cancelled_reason(cx));
+        }
+        self.run_connection_op(cx, "sqlite wal_status", |conn| {
+            let data_version = conn
+                .query_row("PRAGMA data_version", [], |row| row.get::<_, i64>(0))
+                .map_err(|e| SqliteError::Sqlite(e.to_string()))?;
+            Ok(WalStatus {
+                wal_bytes: wal_file_len(conn)?,
+                data_version,
+            })
+        })
+        .await
+    }
+}
+
+// ─── Configuration ───────────────────────────────────────────────────────────
+
+/// A recurring window in which the maintainer escalates to `TRUNCATE`.
+///
+/// The window opens `start` into every `period` of the maintainer clock and
+/// stays open for `length`; windows may wrap past the end of the period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietWindow {
+    period: Duration,
+    start: Duration,
+    length: Duration,
+}
+
+impl QuietWindow {
+    /// Creates a window opening `start` into every `period` for `length`.
+    #[must_use]
+    pub fn new(period: Duration, start: Duration, length: Duration) -> Self {
+        let period = period.max(Duration::from_nanos(1));
+        Self {
+            period,
+            start: Duration::from_nanos(
+                (start.as_nanos() % period.as_nanos()).try_into().unwrap_or(0),
+            ),
+            length: length.min(period),
+        }
+    }
+
+    /// A daily window opening `start` after midnight of the maintainer
+    /// clock (UTC with the default clock).
+    #[must_use]
+    pub fn daily(start: Duration, length: Duration) -> Self {
+        Self::new(Duration::from_secs(24 * 60 * 60), start, length)
+    }
+
+    /// Whether `now` falls inside the window.
+    #[must_use]
+    pub fn contains(&self, now: Time) -> bool {
+        let period = self.period.as_nanos();
+        let phase = u128::from(now.as_nanos()) % period;
+        let since_open = (phase + period - self.start.as_nanos()) % period;
+        since_open < self.length.as_nanos()
+    }
+}
+
+/// [`WalMaintainer`] tuning.
+#[derive(Clone)]
+pub struct WalMaintenanceConfig {
+    size_threshold: u64,
+    poll_interval: Duration,
+    reader_warn_after: Duration,
+    quiet_windows: Vec<QuietWindow>,
+    time_getter: Arc<dyn Fn() -> Time + Send + Sync>,
+}
+
+impl fmt::Debug for WalMaintenanceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalMaintenanceConfig")
+            .field("size_threshold", &self.size_threshold)
+            .field("poll_interval", &self.poll_interval)
+            .field("reader_warn_after", &self.reader_warn_after)
+            .field("quiet_windows", &self.quiet_windows)
+            .field("time_getter", &"<fn>")
+            .finish()
+    }
+}
+
+impl Default for WalMaintenanceConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WalMaintenanceConfig {
+    /// Default WAL size that triggers a `PASSIVE` checkpoint (4 MiB, about
+    /// SQLite's own 1000-page autocheckpoint).
+    pub const DEFAULT_SIZE_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+    /// Creates a configuration with the default threshold, a one second
+    /// poll interval, a one minute long-reader warning and no quiet windows.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            size_threshold: Self::DEFAULT_SIZE_THRESHOLD,
+            poll_interval: Duration::from_secs(1),
+            reader_warn_after: Duration::from_secs(60),
+            quiet_windows: Vec::new(),
+            time_getter: Arc::new(|| Time::from_millis(crate::time::unix_time_millis())),
+        }
+    }
+
+    /// Sets the WAL size in bytes that triggers a `PASSIVE` checkpoint
+    /// (minimum 1).
+    #[must_use]
+    pub fn with_size_threshold(mut self, bytes: u64) -> Self {
+        self.size_threshold = bytes.max(1);
+        self
+    }
+
+    /// Sets how long [`WalMaintainer::run`] waits between passes.
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets how long checkpoints may stay blocked by readers before a
+    /// long-reader warning is emitted.
+    #[must_use]
+    pub fn with_reader_warn_after(mut self, age: Duration) -> Self {
+        self.reader_warn_after = age;
+        self
+    }
+
+    /// Adds a window in which a non-empty WAL is checkpointed in `TRUNCATE`
+    /// mode.
+    #[must_use]
+    pub fn with_quiet_window(mut self, window: QuietWindow) -> Self {
+        self.quiet_windows.push(window);
+        self
+    }
+
+    /// Sets the clock used for quiet windows and reader-block ages.
+    ///
+    /// Defaults to Unix time so [`QuietWindow::daily`] windows are UTC
+    /// aligned.
+    #[must_use]
+    pub fn with_time_getter<F>(mut self, time_getter: F) -> Self
+    where
+        F: Fn() -> Time + Send + Sync + 'static,
+    {
+        self.time_getter = Arc::new(time_getter);
+        self
+    }
+
+    fn now(&self) -> Time {
+        (self.time_getter)()
+    }
+
+    fn in_quiet_window(&self, now: Time) -> bool {
+        self.quiet_windows.iter().any(|window| window.contains(now))
+    }
+}
+
+// ─── Maintainer ──────────────────────────────────────────────────────────────
+
+/// Why a checkpoint ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalCheckpointTrigger {
+    /// The WAL crossed the size threshold.
+    Size,
+    /// A quiet window was open and the WAL was not empty.
+    QuietWindow,
+    /// [`WalMaintainer::checkpoint`] was called.
+    Explicit,
+}
+
+impl WalCheckpointTrigger {
+    /// Stable label used in events.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::QuietWindow => "quiet_window",
+            Self::Explicit => "explicit",
+        }
+    }
+}
+
+/// Maintainer metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalMaintenanceMetrics {
+    /// Maintenance passes run.
+    pub passes: u64,
+    /// `PASSIVE` checkpoints triggered by WAL size.
+    pub passive_checkpoints: u64,
+    /// `TRUNCATE` checkpoints run inside quiet windows.
+    pub truncate_checkpoints: u64,
+    /// Checkpoints requested through [`WalMaintainer::checkpoint`].
+    pub explicit_checkpoints: u64,
+    /// Checkpointed frames, summed over every reported outcome.
+    pub frames_checkpointed: u64,
+    /// Checkpoints that readers kept from completing.
+    pub blocked_by_readers: u64,
+    /// How long checkpoints have been blocked by readers; zero once one
+    /// completes.
+    pub reader_block_age: Duration,
+    /// Long-reader warnings emitted.
+    pub reader_warnings: u64,
+    /// WAL file size seen on the last pass or checkpoint.
+    pub wal_bytes: u64,
+    /// Passes that failed with an error.
+    pub errors: u64,
+    /// Outcome of the most recent checkpoint.
+    pub last_checkpoint: Option<WalCheckpointOutcome>,
+}
+
+/// Result of one maintenance pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalMaintenancePass {
+    /// WAL file size before any checkpoint.
+    pub wal_bytes: u64,
+    /// Why a checkpoint ran, if one did.
+    pub trigger: Option<WalCheckpointTrigger>,
+    /// Checkpoint outcome, if one ran.
+    pub checkpoint: Option<WalCheckpointOutcome>,
+    /// How long checkpoints have been blocked by readers.
+    pub reader_block_age: Duration,
+    /// Whether this pass emitted the long-reader warning.
+    pub reader_warning: bool,
+}
+
+#[derive(Debug, Default)]
+struct MaintainerState {
+    metrics: WalMaintenanceMetrics,
+    /// `data_version` observed before the last threshold checkpoint.
+    checkpointed_version: Option<i64>,
+    /// Whether the last checkpoint left frames behind.
+    incomplete: bool,
+    /// When checkpoints started being blocked by readers.
+    blocked_since: Option<Time>,
+    /// Whether the current blocked episode already produced a warning.
+    warned: bool,
+    last_error: Option<String>,
+}
+
+/// Region-owned WAL checkpoint maintenance for one database.
+///
+/// The WAL file does not shrink after a `PASSIVE` checkpoint, so a
+/// threshold checkpoint only repeats once another connection has committed
+/// (`data_version` changed) or the previous checkpoint was blocked.
+#[derive(Debug)]
+pub struct WalMaintainer {
+    config: WalMaintenanceConfig,
+    state: Mutex<MaintainerState>,
+}
+
+impl WalMaintainer {
+    /// Creates a maintainer.
+    #[must_use]
+    pub fn new(config: WalMaintenanceConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(MaintainerState::default()),
+        }
+    }
+
+    /// The maintainer configuration.
+    #[must_use]
+    pub fn config(&self) -> &WalMaintenanceConfig {
+        &self.config
+    }
+
+    /// Snapshot of the maintainer metrics.
+    #[must_use]
+    pub fn metrics(&self) -> WalMaintenanceMetrics {
+        self.state.lock().metrics
+    }
+
+    /// The last pass error, if any.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.state.lock().last_error.clone()
+    }
+
+    /// Runs maintenance passes until the task is cancelled.
+    ///
+    /// Pass errors are counted in [`WalMaintenanceMetrics::errors`] and do
+    /// not stop the loop; spawn this in the region that owns `conn` so it
+    /// ends with the region.
+    pub async fn run(&self, cx: &Cx, conn: &SqliteConnection) -> Outcome<(), SqliteError> {
+        loop {
+            match self.poll_once(cx, conn).await {
+                Outcome::Ok(_) => {}
+                Outcome::Err(err) => {
+                    #[cfg(feature = "tracing-integration")]
+                    crate::tracing_compat::warn!(error = %err, "WAL maintenance pass failed");
+                    let mut state = self.state.lock();
+                    state.metrics.errors += 1;
+                    state.last_error = Some(err.to_string());
+                }
+                Outcome::Cancelled(reason) => return Outcome::Cancelled(reason),
+                Outcome::Panicked(payload) => return Outcome::Panicked(payload),
+            }
+            if let Err(reason) = sqlite_wait_retry_delay(cx, self.config.poll_interval).await {
+                return Outcome::Cancelled(reason);
+            }
+        }
+    }
+
+    /// Runs one pass: reads the WAL status and checkpoints if the WAL
+    /// crossed the threshold or a quiet window is open.
+    pub async fn poll_once(
+        &self,
+        cx: &Cx,
+        conn: &SqliteConnection,
+    ) -> Outcome<WalMaintenancePass, SqliteError> {
+        let status = try_outcome!(conn.wal_status(cx).await);
+        let now = self.config.now();
+        let trigger = {
+            let mut state = self.state.lock();
+            state.metrics.passes += 1;
+            state.metrics.wal_bytes = status.wal_bytes;
+            let changed = state.checkpointed_version != Some(status.data_version);
+            if status.wal_bytes > 0 && self.config.in_quiet_window(now) {
+                Some(WalCheckpointTrigger::QuietWindow)
+            } else if status.wal_bytes >= self.config.size_threshold
+                && (changed || state.incomplete)
+            {
+                Some(WalCheckpointTrigger::Size)
+            } else {
+                None
+            }
+        };
+
+        let Some(trigger) = trigger else {
+            let (reader_block_age, reader_warning) = self.observe_reader_block(cx, now);
+            return Outcome::Ok(WalMaintenancePass {
+                wal_bytes: status.wal_bytes,
+                trigger: None,
+                checkpoint: None,
+                reader_block_age,
+                reader_warning,
+            });
+        };
+
+        let mode = if trigger == WalCheckpointTrigger::QuietWindow {
+            WalCheckpointMode::Truncate
+        } else {
+            WalCheckpointMode::Passive
+        };
+        let outcome = try_outcome!(conn.wal_checkpoint(cx, mode).await);
+        self.state.lock().checkpointed_version = Some(status.data_version);
+        let (reader_block_age, reader_warning) = self.record(cx, now, trigger, outcome);
+        Outcome::Ok(WalMaintenancePass {
+            wal_bytes: status.wal_bytes,
+            trigger: Some(trigger),
+            checkpoint: Some(outcome),
+            reader_block_age,
+            reader_warning,
+        })
+    }
+
+    /// Runs a checkpoint in `mode` now, regardless of size or quiet
+    /// windows, and records it like a maintenance pass.
+    pub async fn checkpoint(
+        &self,
+        cx: &Cx,
+        conn: &SqliteConnection,
+        mode: WalCheckpointMode,
+    ) -> Outcome<WalCheckpointOutcome, SqliteError> {
+        let outcome = try_outcome!(conn.wal_checkpoint(cx, mode).await);
+        self.record(cx, self.config.now(), WalCheckpointTrigger::Explicit, outcome);
+        Outcome::Ok(outcome)
+    }
+
+    fn record(
+        &self,
+        cx: &Cx,
+        now: Time,
+        trigger: WalCheckpointTrigger,
+        outcome: WalCheckpointOutcome,
+    ) -> (Duration, bool) {
+        let blocked = outcome.blocked_by_readers();
+        {
+            let mut state = self.state.lock();
+            state.incomplete = blocked;
+            let metrics = &mut state.metrics;
+            match trigger {
+                WalCheckpointTrigger::Size => metrics.passive_checkpoints += 1,
+                WalCheckpointTrigger::QuietWindow => metrics.truncate_checkpoints += 1,
+                WalCheckpointTrigger::Explicit => metrics.explicit_checkpoints += 1,
+            }
+            metrics.frames_checkpointed += outcome.checkpointed_frames;
+            metrics.wal_bytes = outcome.wal_bytes;
+            metrics.last_checkpoint = Some(outcome);
+            if blocked {
+                metrics.blocked_by_readers += 1;
+                state.blocked_since.get_or_insert(now);
+            } else {
+                state.blocked_since = None;
+                state.warned = false;
+            }
+        }
+        cx.trace(&format!(
+            "sqlite.wal_checkpoint mode={} trigger={} busy={} log_frames={} \
+             checkpointed_frames={} blocked_by_readers={blocked} wal_bytes={}",
+            outcome.mode,
+            trigger.as_str(),
+            outcome.busy,
+            outcome.log_frames,
+            outcome.checkpointed_frames,
+            outcome.wal_bytes,
+        ));
+        self.observe_reader_block(cx, now)
+    }
+
+    fn observe_reader_block(&self, cx: &Cx, now: Time) -> (Duration, bool) {
+        let (age, warn, pending) = {
+            let mut state = self.state.lock();
+            let age = state.blocked_since.map_or(Duration::ZERO, |since| {
+                Duration::from_nanos(now.duration_since(since))
+            });
+            state.metrics.reader_block_age = age;
+            let warn = state.blocked_since.is_some()
+                && !state.warned
+                && age >= self.config.reader_warn_after;
+            if warn {
+                state.warned = true;
+                state.metrics.reader_warnings += 1;
+            }
+            let pending = state
+                .metrics
+                .last_checkpoint
+                .map_or(0, |outcome| outcome.pending_frames());
+            (age, warn, pending)
+        };
+        if warn {
+            #[cfg(feature = "tracing-integration")]
+            crate::tracing_compat::warn!(
+                age_ms = u64::try_from(age.as_millis()).unwrap_or(u64::MAX),
+                pending_frames = pending,
+                "WAL checkpoints blocked by a long-running reader"
+            );
+            cx.trace(&format!(
+                "sqlite.wal_reader_blocked age_ms={} pending_frames={pending}",
+                age.as_millis()
+            ));
+        }
+        (age, warn)
+    }
+}
+
+// ─── Busy retry ──────────────────────────────────────────────────────────────
+
+/// [`SqliteBusyRetry`] metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SqliteBusyRetryMetrics {
+    /// Operations run through the retry helper.
+    pub operations: u64,
+    /// `SQLITE_BUSY` results observed, including the final one of an
+    /// exhausted operation.
+    pub busy_errors: u64,
+    /// Retries scheduled after a busy result.
+    pub retries: u64,
+    /// Operations that succeeded after at least one retry.
+    pub recovered: u64,
+    /// Operations that ran out of retry attempts.
+    pub exhausted: u64,
+    /// Operations that stopped because the next backoff would overrun the
+    /// deadline.
+    pub deadline_exhausted: u64,
+    /// Total backoff slept.
+    pub backoff: Duration,
+}
+
+/// Retries `SQLITE_BUSY` results with backoff on the runtime clock.
+///
+/// Backoff follows [`RetryPolicy::delay_for`] and is slept through the
+/// [`Cx`] timer driver, so lab runs stay on virtual time and cancellation
+/// interrupts the wait. Retries stop early, returning the busy error, when
+/// the remaining budget could not cover the next backoff. Clones share
+/// metrics.
+#[derive(Debug, Clone)]
+pub struct SqliteBusyRetry {
+    policy: RetryPolicy,
+    metrics: Arc<Mutex<SqliteBusyRetryMetrics>>,
+}
+
+impl Default for SqliteBusyRetry {
+    fn default() -> Self {
+        Self::new(RetryPolicy::default_retry())
+    }
+}
+
+impl SqliteBusyRetry {
+    /// Creates a retry helper with `policy`.
+    #[must_use]
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            metrics: Arc::new(Mutex::new(SqliteBusyRetryMetrics::default())),
+        }
+    }
+
+    /// The retry policy.
+    #[must_use]
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Snapshot of the retry metrics.
+    #[must_use]
+    pub fn metrics(&self) -> SqliteBusyRetryMetrics {
+        *self.metrics.lock()
+    }
+
+    /// Runs `op`, retrying while it fails with `SQLITE_BUSY`.
+    pub async fn run<T, F, Fut>(&self, cx: &Cx, mut op: F) -> Outcome<T, SqliteError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Outcome<T, SqliteError>>,
+    {
+        self.metrics.lock().operations += 1;
+        let mut attempt = 0u32;
+        loop {
+            let err = match op().await {
+                Outcome::Err(err) if err.is_busy() => err,
+                outcome => {
+                    if attempt > 0 && matches!(outcome, Outcome::Ok(_)) {
+                        self.metrics.lock().recovered += 1;
+                    }
+                    return outcome;
+                }
+            };
+
+            let delay = self.policy.delay_for(attempt);
+            let out_of_attempts = attempt >= self.policy.max_retries;
+            let out_of_budget = !out_of_attempts
+                && crate::database::remaining_budget(cx)
+                    .is_some_and(|remaining| remaining <= delay);
+            {
+                let mut metrics = self.metrics.lock();
+                metrics.busy_errors += 1;
+                if out_of_attempts {
+                    metrics.exhausted += 1;
+                } else if out_of_budget {
+                    metrics.deadline_exhausted += 1;
+                } else {
+                    metrics.retries += 1;
+                    metrics.backoff += delay;
+                }
+            }
+            if out_of_attempts || out_of_budget {
+                let reason = if out_of_attempts { "attempts" } else { "deadline" };
+                cx.trace(&format!(
+                    "sqlite.busy_retry_exhausted reason={reason} attempts={}",
+                    attempt + 1
+                ));
+                return Outcome::Err(err);
+            }
+
+            cx.trace(&format!(
+                "sqlite.busy_retry attempt={} delay_ms={}",
+                attempt + 1,
+                delay.as_millis()
+            ));
+            if let Err(reason) = sqlite_wait_retry_delay(cx, delay).await {
+                return Outcome::Cancelled(reason);
+            }
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::database::SqliteValue;
+    use crate::time::{TimerDriverHandle, VirtualClock};
+    use crate::trace::{TraceBufferHandle, TraceData, TraceEventKind};
+    use crate::types::Budget;
+    use crate::{RegionId, TaskId};
+    use futures_lite::future::block_on;
+    use std::cell::{Cell, RefCell};
+    use std::path::Path;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::task::{Context, Poll, Waker};
+    use tempfile::tempdir;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn traced_cx() -> (Cx, TraceBufferHandle) {
+        let cx = Cx::new(
+            RegionId::new_for_test(1, 0),
+            TaskId::new_for_test(1, 0),
+            Budget::INFINITE,
+        );
+        let trace = TraceBufferHandle::new(256);
+        cx.set_trace_buffer(trace.clone());
+        (cx, trace)
+    }
+
+    fn virtual_cx(budget: Budget) -> (Cx, Arc<VirtualClock>, TimerDriverHandle) {
+        let clock = Arc::new(VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(Arc::clone(&clock));
+        let cx = Cx::new_with_drivers(
+            RegionId::new_for_test(2, 0),
+            TaskId::new_for_test(2, 0),
+            budget,
+            None,
+            None,
+            None,
+            Some(timer.clone()),
+            None,
+        );
+        (cx, clock, timer)
+    }
+
+    /// Polls `fut` to completion, jumping the virtual clock to each timer
+    /// deadline and yielding real time only while blocking-pool work runs.
+    fn drive<F: Future>(clock: &VirtualClock, timer: &TimerDriverHandle, fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut task_cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut task_cx) {
+                return output;
+            }
+            if let Some(deadline) = timer.next_deadline() {
+                clock.advance_to(deadline);
+                timer.process_timers();
+            } else {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    fn trace_messages(trace: &TraceBufferHandle, prefix: &str) -> Vec<String> {
+        trace
+            .snapshot()
+            .iter()
+            .filter(|e| e.kind == TraceEventKind::UserTrace)
+            .filter_map(|e| match &e.data {
+                TraceData::Message(msg) if msg.starts_with(prefix) => Some(msg.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn open(cx: &Cx, path: &Path) -> SqliteConnection {
+        block_on(SqliteConnection::open(cx, path)).expect("open database")
+    }
+
+    fn setup_writer(cx: &Cx, path: &Path) -> SqliteConnection {
+        let writer = open(cx, path);
+        block_on(writer.execute_batch_unchecked(
+            cx,
+            "PRAGMA wal_autocheckpoint=0; \
+             CREATE TABLE t (id INTEGER PRIMARY KEY, payload BLOB)",
+        ))
+        .expect("create table");
+        writer
+    }
+
+    fn write_rows(cx: &Cx, writer: &SqliteConnection, rows: usize) {
+        for _ in 0..rows {
+            block_on(writer.execute(
+                cx,
+                "INSERT INTO t (payload) VALUES (?1)",
+                &[SqliteValue::Blob(vec![7; 4096])],
+            ))
+            .expect("insert row");
+        }
+    }
+
+    fn wal_len(path: &Path) -> u64 {
+        let mut wal = path.as_os_str().to_owned();
+        wal.push("-wal");
+        std::fs::metadata(wal).map(|meta| meta.len()).unwrap_or(0)
+    }
+
+    fn manual_clock(config: WalMaintenanceConfig) -> (WalMaintenanceConfig, Arc<AtomicU64>) {
+        let millis = Arc::new(AtomicU64::new(0));
+        let clock = Arc::clone(&millis);
+        let config =
+            config.with_time_getter(move || Time::from_millis(clock.load(Ordering::SeqCst)));
+        (config, millis)
+    }
+
+    #[test]
+    fn wal_size_threshold_triggers_passive_checkpoint() {
+        init_test("wal_size_threshold_triggers_passive_checkpoint");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("size.db");
+        let (cx, trace) = traced_cx();
+        let writer = setup_writer(&cx, &path);
+        let conn = open(&cx, &path);
+        let maintainer =
+            WalMaintainer::new(WalMaintenanceConfig::new().with_size_threshold(64 * 1024));
+
+        let idle = block_on(maintainer.poll_once(&cx, &conn)).expect("idle pass");
+        crate::assert_with_log!(
+            idle.checkpoint.is_none(),
+            "small WAL is left alone",
+            true,
+            idle.wal_bytes
+        );
+
+        write_rows(&cx, &writer, 64);
+        let pass = block_on(maintainer.poll_once(&cx, &conn)).expect("threshold pass");
+        let outcome = pass.checkpoint.expect("threshold checkpoint");
+        crate::assert_with_log!(
+            pass.wal_bytes >= 64 * 1024 && pass.trigger == Some(WalCheckpointTrigger::Size),
+            "threshold crossed",
+            ">= 64KiB",
+            pass.wal_bytes
+        );
+        crate::assert_with_log!(
+            outcome.mode == WalCheckpointMode::Passive
+                && outcome.log_frames > 0
+                && outcome.checkpointed_frames == outcome.log_frames
+                && !outcome.blocked_by_readers(),
+            "passive checkpoint completes",
+            true,
+            outcome
+        );
+
+        let repeat = block_on(maintainer.poll_once(&cx, &conn)).expect("repeat pass");
+        crate::assert_with_log!(
+            repeat.checkpoint.is_none(),
+            "unchanged WAL is not re-checkpointed",
+            true,
+            repeat.trigger
+        );
+        let metrics = maintainer.metrics();
+        crate::assert_with_log!(
+            metrics.passes == 3
+                && metrics.passive_checkpoints == 1
+                && metrics.frames_checkpointed == outcome.checkpointed_frames,
+            "metrics",
+            (3, 1, outcome.checkpointed_frames),
+            metrics
+        );
+        let events = trace_messages(&trace, "sqlite.wal_checkpoint");
+        crate::assert_with_log!(
+            events.len() == 1 && events[0].contains("mode=PASSIVE trigger=size"),
+            "checkpoint event",
+            1,
+            events
+        );
+        crate::test_complete!("wal_size_threshold_triggers_passive_checkpoint");
+    }
+
+    #[test]
+    fn quiet_window_and_explicit_checkpoint_escalate_to_truncate() {
+        init_test("quiet_window_and_explicit_checkpoint_escalate_to_truncate");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("quiet.db");
+        let (cx, trace) = traced_cx();
+        let writer = setup_writer(&cx, &path);
+        let conn = open(&cx, &path);
+        let window = QuietWindow::new(
+            Duration::from_secs(60),
+            Duration::from_secs(30),
+            Duration::from_secs(10),
+        );
+        let (config, millis) = manual_clock(
+            WalMaintenanceConfig::new()
+                .with_size_threshold(16 * 1024)
+                .with_quiet_window(window),
+        );
+        let maintainer = WalMaintainer::new(config);
+
+        write_rows(&cx, &writer, 16);
+        let busy_hours = block_on(maintainer.poll_once(&cx, &conn)).expect("busy pass");
+        crate::assert_with_log!(
+            busy_hours.trigger == Some(WalCheckpointTrigger::Size) && wal_len(&path) > 0,
+            "outside the window the checkpoint is passive",
+            Some(WalCheckpointTrigger::Size),
+            busy_hours.trigger
+        );
+
+        millis.store(95_000, Ordering::SeqCst);
+        let quiet = block_on(maintainer.poll_once(&cx, &conn)).expect("quiet pass");
+        let outcome = quiet.checkpoint.expect("quiet checkpoint");
+        crate::assert_with_log!(
+            quiet.trigger == Some(WalCheckpointTrigger::QuietWindow)
+                && outcome.mode == WalCheckpointMode::Truncate
+                && !outcome.busy
+                && outcome.wal_bytes == 0
+                && wal_len(&path) == 0,
+            "quiet window truncates the WAL",
+            0,
+            outcome
+        );
+
+        millis.store(0, Ordering::SeqCst);
+        write_rows(&cx, &writer, 2);
+        let explicit = block_on(maintainer.checkpoint(&cx, &conn, WalCheckpointMode::Truncate))
+            .expect("explicit checkpoint");
+        crate::assert_with_log!(
+            explicit.wal_bytes == 0 && wal_len(&path) == 0,
+            "explicit truncate",
+            0,
+            explicit
+        );
+        let metrics = maintainer.metrics();
+        crate::assert_with_log!(
+            metrics.passive_checkpoints == 1
+                && metrics.truncate_checkpoints == 1
+                && metrics.explicit_checkpoints == 1,
+            "one checkpoint per trigger",
+            (1, 1, 1),
+            metrics
+        );
+        let events = trace_messages(&trace, "sqlite.wal_checkpoint mode=TRUNCATE");
+        crate::assert_with_log!(events.len() == 2, "truncate events", 2, events);
+        crate::test_complete!("quiet_window_and_explicit_checkpoint_escalate_to_truncate");
+    }
+
+    #[test]
+    fn long_reader_blocks_checkpoint_and_warns_once() {
+        init_test("long_reader_blocks_checkpoint_and_warns_once");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("reader.db");
+        let (cx, trace) = traced_cx();
+        let writer = setup_writer(&cx, &path);
+        let conn = open(&cx, &path);
+        let (config, millis) = manual_clock(
+            WalMaintenanceConfig::new()
+                .with_size_threshold(1)
+                .with_reader_warn_after(Duration::from_secs(30)),
+        );
+        let maintainer = WalMaintainer::new(config);
+        write_rows(&cx, &writer, 1);
+        let clean = block_on(maintainer.poll_once(&cx, &conn)).expect("clean pass");
+        crate::assert_with_log!(
+            clean.checkpoint.is_some_and(|outcome| !outcome.blocked_by_readers()),
+            "no reader, checkpoint completes",
+            true,
+            clean
+        );
+
+        let reader = rusqlite::Connection::open(&path).expect("reader");
+        reader.execute_batch("BEGIN").expect("begin read");
+        let _: i64 = reader
+            .query_row("SELECT count(*) FROM t", [], |row| row.get(0))
+            .expect("read snapshot");
+        write_rows(&cx, &writer, 8);
+
+        let blocked = block_on(maintainer.poll_once(&cx, &conn)).expect("blocked pass");
+        let outcome = blocked.checkpoint.expect("blocked checkpoint");
+        crate::assert_with_log!(
+            outcome.blocked_by_readers() && outcome.pending_frames() > 0,
+            "reader pins frames",
+            true,
+            outcome
+        );
+        crate::assert_with_log!(
+            blocked.reader_block_age == Duration::ZERO && !blocked.reader_warning,
+            "fresh block does not warn",
+            false,
+            blocked.reader_warning
+        );
+
+        millis.store(40_000, Ordering::SeqCst);
+        let aged = block_on(maintainer.poll_once(&cx, &conn)).expect("aged pass");
+        crate::assert_with_log!(
+            aged.reader_block_age == Duration::from_secs(40) && aged.reader_warning,
+            "long reader warning",
+            Duration::from_secs(40),
+            aged.reader_block_age
+        );
+        millis.store(50_000, Ordering::SeqCst);
+        let again = block_on(maintainer.poll_once(&cx, &conn)).expect("repeat pass");
+        crate::assert_with_log!(!again.reader_warning, "warns once", false, again);
+        let warnings = trace_messages(&trace, "sqlite.wal_reader_blocked");
+        crate::assert_with_log!(
+            warnings.len() == 1 && warnings[0].contains("age_ms=40000"),
+            "one warning event",
+            1,
+            warnings
+        );
+
+        reader.execute_batch("COMMIT").expect("end read");
+        millis.store(60_000, Ordering::SeqCst);
+        let released = block_on(maintainer.poll_once(&cx, &conn)).expect("released pass");
+        crate::assert_with_log!(
+            released
+                .checkpoint
+                .is_some_and(|outcome| !outcome.blocked_by_readers())
+                && released.reader_block_age == Duration::ZERO,
+            "checkpoint completes once the reader ends",
+            true,
+            released
+        );
+        let metrics = maintainer.metrics();
+        crate::assert_with_log!(
+            metrics.blocked_by_readers == 3
+                && metrics.reader_warnings == 1
+                && metrics.reader_block_age == Duration::ZERO,
+            "reader metrics",
+            (3, 1),
+            metrics
+        );
+        crate::test_complete!("long_reader_blocks_checkpoint_and_warns_once");
+    }
+
+    #[test]
+    fn busy_retry_backs_off_on_virtual_clock_until_writer_releases() {
+        init_test("busy_retry_backs_off_on_virtual_clock_until_writer_releases");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("busy.db");
+        let (cx, clock, timer) = virtual_cx(Budget::INFINITE);
+        let conn = drive(&clock, &timer, SqliteConnection::open(&cx, &path)).expect("open");
+        drive(&clock, &timer, conn.set_busy_timeout(&cx, Duration::ZERO)).expect("timeout");
+        drive(
+            &clock,
+            &timer,
+            conn.execute_batch(&cx, "CREATE TABLE t (v INTEGER)"),
+        )
+        .expect("create table");
+
+        let holder = rusqlite::Connection::open(&path).expect("holder");
+        holder
+            .execute_batch("BEGIN IMMEDIATE; INSERT INTO t (v) VALUES (0)")
+            .expect("hold write lock");
+        let holder = RefCell::new(Some(holder));
+        let attempts = Cell::new(0u32);
+        let retry = SqliteBusyRetry::new(RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(80),
+        });
+
+        let start = timer.now();
+        let (conn_ref, cx_ref, holder_ref, attempts_ref) = (&conn, &cx, &holder, &attempts);
+        let inserted = drive(
+            &clock,
+            &timer,
+            retry.run(&cx, move || {
+                let attempt = attempts_ref.get();
+                attempts_ref.set(attempt + 1);
+                if attempt == 2
+                    && let Some(holder) = holder_ref.borrow_mut().take()
+                {
+                    holder.execute_batch("COMMIT").expect("release write lock");
+                }
+                conn_ref.execute(cx_ref, "INSERT INTO t (v) VALUES (1)", &[])
+            }),
+        );
+        crate::assert_with_log!(
+            matches!(inserted, Outcome::Ok(1)),
+            "insert succeeds after the writer commits",
+            "Ok(1)",
+            format!("{inserted:?}")
+        );
+        let elapsed = Duration::from_nanos(timer.now().duration_since(start));
+        crate::assert_with_log!(
+            elapsed == Duration::from_millis(30),
+            "10ms + 20ms of virtual backoff",
+            Duration::from_millis(30),
+            elapsed
+        );
+        let metrics = retry.metrics();
+        crate::assert_with_log!(
+            metrics.operations == 1
+                && metrics.busy_errors == 2
+                && metrics.retries == 2
+                && metrics.recovered == 1
+                && metrics.exhausted == 0
+                && metrics.backoff == Duration::from_millis(30),
+            "retry metrics",
+            (1, 2, 2, 1),
+            metrics
+        );
+        crate::test_complete!("busy_retry_backs_off_on_virtual_clock_until_writer_releases");
+    }
+
+    #[test]
+    fn busy_retry_stops_before_backoff_overruns_deadline() {
+        init_test("busy_retry_stops_before_backoff_overruns_deadline");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("deadline.db");
+        let budget = Budget::INFINITE.with_deadline(Time::from_millis(25));
+        let (cx, clock, timer) = virtual_cx(budget);
+        let conn = drive(&clock, &timer, SqliteConnection::open(&cx, &path)).expect("open");
+        drive(&clock, &timer, conn.set_busy_timeout(&cx, Duration::ZERO)).expect("timeout");
+        drive(
+            &clock,
+            &timer,
+            conn.execute_batch(&cx, "CREATE TABLE t (v INTEGER)"),
+        )
+        .expect("create table");
+
+        let holder = rusqlite::Connection::open(&path).expect("holder");
+        holder
+            .execute_batch("BEGIN IMMEDIATE; INSERT INTO t (v) VALUES (0)")
+            .expect("hold write lock");
+        let retry = SqliteBusyRetry::new(RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_secs(1),
+        });
+
+        let (conn_ref, cx_ref) = (&conn, &cx);
+        let result = drive(
+            &clock,
+            &timer,
+            retry.run(&cx, move || {
+                conn_ref.execute(cx_ref, "INSERT INTO t (v) VALUES (1)", &[])
+            }),
+        );
+        crate::assert_with_log!(
+            matches!(&result, Outcome::Err(err) if err.is_busy()),
+            "busy error surfaces once the deadline cannot cover the backoff",
+            "Err(busy)",
+            format!("{result:?}")
+        );
+        crate::assert_with_log!(
+            timer.now() == Time::from_millis(10),
+            "only the first 10ms backoff was slept",
+            Time::from_millis(10),
+            timer.now()
+        );
+        let metrics = retry.metrics();
+        crate::assert_with_log!(
+            metrics.retries == 1
+                && metrics.busy_errors == 2
+                && metrics.deadline_exhausted == 1
+                && metrics.exhausted == 0,
+            "deadline metrics",
+            (1, 2, 1),
+            metrics
+        );
+        drop(holder);
+        crate::test_complete!("busy_retry_stops_before_backoff_overruns_deadline");
+    }
+}
@@ -6,17 +6,25 @@
 //! intermediate symbols using the precode constraints and LT repair rows, then
 //! reconstitutes source symbols deterministically for testing.
 
+use crate::cx::Cx;
+use crate::decoding::reorder::{Admission, FlushReason, ReorderBuffer};
 use crate::error::{Error, ErrorKind};
 use crate::raptorq::decoder::{
     DecodeError as RaptorDecodeError, InactivationDecoder, RankStatus, ReceivedSymbol,
 };
 use crate::raptorq::systematic::{SystematicError, SystematicParams};
+use crate::runtime::reclaim::{ReclaimFuture, Reclaimable};
 use crate::security::{AuthenticatedSymbol, SecurityContext};
+use crate::time::wall_now;
 use crate::types::symbol_set::{InsertResult, SymbolSet, ThresholdConfig};
-use crate::types::{ObjectId, ObjectParams, Symbol, SymbolId, SymbolKind};
+use crate::types::{ObjectId, ObjectParams, Symbol, SymbolId, SymbolKind, Time};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
+pub mod reorder;
+
+pub use reorder::{REORDER_DISTANCE_BUCKETS, ReorderConfig, ReorderStats};
+
 const REPAIR_RETENTION_MIN_SLACK: usize = 128;
 const REPAIR_RETENTION_MAX_SLACK: usize = 2048;
 
@@ -105,6 +113,14 @@ pub enum RejectReason {
     InvalidMetadata,
     /// Memory or buffer limit reached.
     MemoryLimitReached,
+    /// A batched symbol's Merkle authentication path does not lead to its
+    /// batch root.
+    BadPath,
+    /// The symbol names a batch the verifier no longer tracks.
+    UnknownBatch,
+    /// The batch root tag failed verification, or the root disagrees with the
+    /// one already verified for that batch.
+    BatchRootInvalid,
 }
 
 /// Result of feeding a symbol into the decoder.
@@ -209,10 +225,9 @@ pub(crate) struct BlockDecodeOutcome {
     sbn: u8,
     input_symbols: usize,
     retain_decoded_block: bool,
-    // Read only by the native audit/telemetry consumers; the browser
-    // profile records outcomes without re-reading these fields.
-    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     kind: BlockDecodeKind,
+    // Read only by the native audit/telemetry consumers; the browser
+    // profile records outcomes without re-reading this field.
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     elapsed: Duration,
     resolution: BlockDecodeResolution,
@@ -439,6 +454,12 @@ pub struct DecodingProgress {
     pub symbols_received: usize,
     /// Estimated symbols needed to complete decode.
     pub symbols_needed_estimate: usize,
+    /// Block solves that ran RaptorQ elimination instead of completing from
+    /// source symbols alone.
+    pub elimination_attempts: u64,
+    /// Reordering buffer statistics (all zero unless
+    /// [`DecodingPipeline::with_reordering`] is in use).
+    pub reorder: ReorderStats,
 }
 
 /// Per-block status.
@@ -534,6 +555,8 @@ pub struct DecodingPipeline {
     /// per-symbol log lines while still giving operators a visible
     /// signal that auth is off.
     verify_auth_disabled_warned: bool,
+    elimination_attempts: u64,
+    reorder: Option<ReorderBuffer>,
 }
 
 impl DecodingPipeline {
@@ -568,9 +591,22 @@ impl DecodingPipeline {
             auth_context: None,
             skipped_verifications: 0,
             verify_auth_disabled_warned: false,
+            elimination_attempts: 0,
+            reorder: None,
         }
     }
 
+    /// Puts a bounded reordering buffer in front of [`Self::feed_at`].
+    ///
+    /// Symbols ahead of their block's next expected ESI are held until the gap
+    /// fills, a bound in `config` is hit, or the block could finish. [`Self::feed`]
+    /// keeps bypassing the buffer.
+    #[must_use]
+    pub fn with_reordering(mut self, config: ReorderConfig) -> Self {
+        self.reorder = Some(ReorderBuffer::new(config));
+        self
+    }
+
     /// br-asupersync-f4mdcr: total number of `feed()` calls that
     /// accepted a symbol with authentication INTENTIONALLY skipped
     /// because `config.verify_auth = false`. Operators can scrape
@@ -914,6 +950,128 @@ impl DecodingPipeline {
         symbols.map(|symbol| self.feed(symbol)).collect()
     }
 
+    /// Feeds a symbol through the reordering buffer at virtual time `now`.
+    ///
+    /// Returns one result per symbol released to the decoder by this call, in
+    /// release order; an empty vector means the symbol is being held. Holds
+    /// that expired by `now` are released as part of the call. Without
+    /// [`Self::with_reordering`] this is [`Self::feed`].
+    ///
+    /// A block is flushed as soon as it could finish: when every source
+    /// symbol is among the received and held symbols, or when the block is
+    /// already decoded. Reaching the repair threshold does not count, since
+    /// finishing from repair symbols is the elimination the hold is trying to
+    /// avoid; `max_hold` bounds the extra latency in that case.
+    pub fn feed_at(
+        &mut self,
+        auth_symbol: AuthenticatedSymbol,
+        now: Time,
+    ) -> Vec<Result<SymbolAcceptResult, DecodingError>> {
+        let Some(buffer) = self.reorder.as_mut() else {
+            return vec![self.feed(auth_symbol)];
+        };
+        let sbn = auth_symbol.symbol().sbn();
+        if self.completed_blocks.contains(&sbn) {
+            buffer.pass_through();
+            return vec![self.feed(auth_symbol)];
+        }
+
+        let mut results = Vec::new();
+        let mut released = Vec::new();
+        match buffer.admit(auth_symbol, now) {
+            Admission::Release(symbol) => {
+                released.push(symbol);
+                released.extend(buffer.take_in_order(sbn, now));
+            }
+            Admission::Held => {}
+            Admission::Duplicate => results.push(Ok(SymbolAcceptResult::Duplicate)),
+        }
+        if buffer.over_capacity() {
+            released.extend(buffer.take_all(now, FlushReason::Capacity));
+        }
+        released.extend(buffer.take_expired(now));
+        results.extend(released.into_iter().map(|symbol| self.feed(symbol)));
+        self.flush_completable_block(sbn, now, &mut results);
+        results
+    }
+
+    /// Releases held symbols whose `max_hold` elapsed by `now`.
+    pub fn poll_reorder(&mut self, now: Time) -> Vec<Result<SymbolAcceptResult, DecodingError>> {
+        let Some(buffer) = self.reorder.as_mut() else {
+            return Vec::new();
+        };
+        let released = buffer.take_expired(now);
+        released.into_iter().map(|symbol| self.feed(symbol)).collect()
+    }
+
+    /// Releases every held symbol in `(sbn, esi)` order, e.g. at end of stream.
+    pub fn flush_reorder(&mut self, now: Time) -> Vec<Result<SymbolAcceptResult, DecodingError>> {
+        let Some(buffer) = self.reorder.as_mut() else {
+            return Vec::new();
+        };
+        let released = buffer.take_all(now, FlushReason::Manual);
+        released.into_iter().map(|symbol| self.feed(symbol)).collect()
+    }
+
+    /// Releases held symbols to the decoder, oldest blocks first, until at
+    /// least `target_bytes` of held payload are released.
+    ///
+    /// This is how the reordering buffer gives memory back under pressure;
+    /// the pipeline also implements [`Reclaimable`] through a mutex.
+    pub fn shed_reorder(
+        &mut self,
+        now: Time,
+        target_bytes: usize,
+    ) -> Vec<Result<SymbolAcceptResult, DecodingError>> {
+        let Some(buffer) = self.reorder.as_mut() else {
+            return Vec::new();
+        };
+        let released = buffer.take_for_pressure(now, target_bytes);
+        released.into_iter().map(|symbol| self.feed(symbol)).collect()
+    }
+
+    fn reorder_buffered_bytes(&self) -> usize {
+        self.reorder
+            .as_ref()
+            .map_or(0, |buffer| buffer.stats().buffered_bytes)
+    }
+
+    /// Earliest virtual time at which [`Self::poll_reorder`] releases symbols.
+    #[must_use]
+    pub fn reorder_deadline(&self) -> Option<Time> {
+        self.reorder.as_ref().and_then(ReorderBuffer::deadline)
+    }
+
+    fn flush_completable_block(
+        &mut self,
+        sbn: u8,
+        now: Time,
+        results: &mut Vec<Result<SymbolAcceptResult, DecodingError>>,
+    ) {
+        let Some(buffer) = self.reorder.as_ref() else {
+            return;
+        };
+        if !buffer.holds_block(sbn) {
+            return;
+        }
+        let completable = self.completed_blocks.contains(&sbn)
+            || self.block_plan(sbn).is_some_and(|plan| {
+                let received = self
+                    .block_symbol_counts
+                    .get(&sbn)
+                    .map_or(0, |counts| counts.source_symbols);
+                received + buffer.held_source_symbols(sbn, plan.k) >= plan.k
+            });
+        if !completable {
+            return;
+        }
+        let Some(buffer) = self.reorder.as_mut() else {
+            return;
+        };
+        let released = buffer.take_block(sbn, now, FlushReason::Completion);
+        results.extend(released.into_iter().map(|symbol| self.feed(symbol)));
+    }
+
     /// Returns true if all expected blocks are decoded.
     #[must_use]
     pub fn is_complete(&self) -> bool {
@@ -937,6 +1095,11 @@ impl DecodingPipeline {
             blocks_total,
             symbols_received,
             symbols_needed_estimate,
+            elimination_attempts: self.elimination_attempts,
+            reorder: self
+                .reorder
+                .as_ref()
+                .map_or_else(ReorderStats::default, ReorderBuffer::stats),
         }
     }
 
@@ -1163,10 +1326,11 @@ impl DecodingPipeline {
             sbn,
             input_symbols: _,
             retain_decoded_block,
-            kind: _,
+            kind,
             elapsed: _,
             resolution,
         } = outcome;
+        self.record_decode_kind(kind);
         match resolution {
             BlockDecodeResolution::Complete(block_data) => {
                 self.mark_block_complete(sbn, retain_decoded_block.then(|| block_data.clone()));
@@ -1219,10 +1383,11 @@ impl DecodingPipeline {
             sbn,
             input_symbols,
             retain_decoded_block,
-            kind: _,
+            kind,
             elapsed: _,
             resolution,
         } = outcome;
+        self.record_decode_kind(kind);
         if self.completed_blocks.contains(&sbn) {
             return DeferredSymbolAcceptResult::Immediate(SymbolAcceptResult::Rejected(
                 RejectReason::BlockAlreadyDecoded,
@@ -1338,6 +1503,12 @@ impl DecodingPipeline {
         self.block_symbol_counts.remove(&sbn);
     }
 
+    fn record_decode_kind(&mut self, kind: BlockDecodeKind) {
+        if matches!(kind, BlockDecodeKind::RaptorQRepair) {
+            self.elimination_attempts = self.elimination_attempts.saturating_add(1);
+        }
+    }
+
     fn block_plan(&self, sbn: u8) -> Option<&BlockPlan> {
         let idx = self.block_plan_by_sbn[usize::from(sbn)]?;
         self.block_plans.as_ref()?.get(idx)
@@ -1386,6 +1557,22 @@ impl DecodingPipeline {
     }
 }
 
+impl Reclaimable for parking_lot::Mutex<DecodingPipeline> {
+    /// Sheds held reordering symbols to the decoder. Per-symbol results are
+    /// discarded; finished blocks stay visible through
+    /// [`DecodingPipeline::progress`].
+    fn reclaim(&self, target_bytes: u64) -> ReclaimFuture<'_> {
+        let now = Cx::current().map_or_else(wall_now, |cx| cx.now());
+        let target_bytes = usize::try_from(target_bytes).unwrap_or(usize::MAX);
+        let mut pipeline = self.lock();
+        let before = pipeline.reorder_buffered_bytes();
+        let _ = pipeline.shed_reorder(now, target_bytes);
+        let freed = before - pipeline.reorder_buffered_bytes();
+        drop(pipeline);
+        Box::pin(std::future::ready(u64::try_from(freed).unwrap_or(u64::MAX)))
+    }
+}
+
 #[derive(Debug, Clone)]
 struct BlockPlan {
     sbn: u8,
@@ -4323,4 +4510,206 @@ mod tests {
         }
         crate::test_complete!("honest_symbols_never_inconsistent_across_stale_requeue_cycles");
     }
+
+    fn reorder_fixture(object_id: ObjectId) -> (DecodingPipeline, Vec<u8>, Vec<Symbol>) {
+        let config = encoding_config();
+        let mut encoder = EncodingPipeline::new(config.clone(), pool());
+        let data: Vec<u8> = (0..1024u32).map(|i| (i * 31 % 251) as u8).collect();
+        let symbols = encoder
+            .encode_with_repair(object_id, &data, 4)
+            .map(|res| res.expect("encode").into_symbol())
+            .collect();
+        let decoder = decoder_with_params(&config, object_id, data.len(), 1.0, 0);
+        (decoder, data, symbols)
+    }
+
+    fn symbol_with_esi(symbols: &[Symbol], esi: u32) -> AuthenticatedSymbol {
+        let symbol = symbols
+            .iter()
+            .find(|symbol| symbol.esi() == esi)
+            .expect("symbol with esi");
+        AuthenticatedSymbol::new_unauthenticated(symbol.clone())
+    }
+
+    /// K = 4: repairs 4 and 5 overtake the late sources 2 and 3.
+    const OVERTAKEN_ARRIVAL: [u32; 6] = [0, 1, 4, 5, 2, 3];
+
+    #[test]
+    fn reorder_buffer_avoids_elimination_for_overtaken_sources() {
+        init_test("reorder_buffer_avoids_elimination_for_overtaken_sources");
+        let (mut plain, _, symbols) = reorder_fixture(ObjectId::new_for_test(700));
+        for esi in OVERTAKEN_ARRIVAL {
+            let _ = plain.feed(symbol_with_esi(&symbols, esi)).expect("feed");
+        }
+        let plain_attempts = plain.progress().elimination_attempts;
+        crate::assert_with_log!(
+            plain_attempts >= 1,
+            "unbuffered decoder eliminates",
+            ">= 1",
+            plain_attempts
+        );
+
+        let (decoder, _, _) = reorder_fixture(ObjectId::new_for_test(700));
+        let mut decoder = decoder.with_reordering(ReorderConfig::default());
+        for esi in OVERTAKEN_ARRIVAL {
+            for result in decoder.feed_at(symbol_with_esi(&symbols, esi), Time::ZERO) {
+                let _ = result.expect("feed");
+            }
+        }
+        let progress = decoder.progress();
+        crate::assert_with_log!(
+            progress.blocks_complete == 1,
+            "block complete",
+            1,
+            progress.blocks_complete
+        );
+        crate::assert_with_log!(
+            progress.elimination_attempts == 0,
+            "buffered decoder uses source fast path",
+            0,
+            progress.elimination_attempts
+        );
+        let histogram = progress.reorder.distance_histogram;
+        crate::assert_with_log!(
+            histogram == [4, 0, 2, 0, 0, 0],
+            "reorder distances",
+            [4, 0, 2, 0, 0, 0],
+            histogram
+        );
+        crate::test_complete!("reorder_buffer_avoids_elimination_for_overtaken_sources");
+    }
+
+    #[test]
+    fn reorder_buffer_flushes_after_max_hold() {
+        init_test("reorder_buffer_flushes_after_max_hold");
+        let (decoder, _, symbols) = reorder_fixture(ObjectId::new_for_test(701));
+        let mut decoder = decoder.with_reordering(ReorderConfig {
+            max_hold: Duration::from_millis(5),
+            ..ReorderConfig::default()
+        });
+
+        let released = decoder.feed_at(symbol_with_esi(&symbols, 0), Time::ZERO);
+        crate::assert_with_log!(released.len() == 1, "in-order release", 1, released.len());
+        let released = decoder.feed_at(symbol_with_esi(&symbols, 2), Time::from_millis(1));
+        crate::assert_with_log!(released.is_empty(), "gap holds esi 2", 0, released.len());
+
+        let deadline = decoder.reorder_deadline();
+        crate::assert_with_log!(
+            deadline == Some(Time::from_millis(6)),
+            "deadline",
+            Some(Time::from_millis(6)),
+            deadline
+        );
+        let released = decoder.poll_reorder(Time::from_millis(5));
+        crate::assert_with_log!(released.is_empty(), "held for 4ms", 0, released.len());
+        let released = decoder.poll_reorder(Time::from_millis(6));
+        crate::assert_with_log!(released.len() == 1, "released after 5ms", 1, released.len());
+
+        let stats = decoder.progress().reorder;
+        crate::assert_with_log!(
+            stats.flushed_hold_time == 1 && stats.buffered_symbols == 0,
+            "hold-time flush",
+            (1, 0),
+            (stats.flushed_hold_time, stats.buffered_symbols)
+        );
+        crate::assert_with_log!(
+            stats.max_hold == Duration::from_millis(5),
+            "max hold",
+            Duration::from_millis(5),
+            stats.max_hold
+        );
+        crate::test_complete!("reorder_buffer_flushes_after_max_hold");
+    }
+
+    #[test]
+    fn reorder_buffer_flushes_held_symbols_once_block_decodes() {
+        init_test("reorder_buffer_flushes_held_symbols_once_block_decodes");
+        let (decoder, _, symbols) = reorder_fixture(ObjectId::new_for_test(702));
+        let mut decoder = decoder.with_reordering(ReorderConfig::default());
+
+        let mut released = Vec::new();
+        // Repairs 5 and 6 stay behind the ESI 4 gap after the sources drain.
+        for esi in [0, 5, 6, 1, 2, 3] {
+            released.extend(decoder.feed_at(symbol_with_esi(&symbols, esi), Time::ZERO));
+        }
+        crate::assert_with_log!(released.len() == 6, "all released", 6, released.len());
+        let stats = decoder.progress().reorder;
+        crate::assert_with_log!(
+            stats.flushed_completion == 2 && stats.buffered_symbols == 0,
+            "held repairs flushed without a clock advance",
+            (2, 0),
+            (stats.flushed_completion, stats.buffered_symbols)
+        );
+        let deadline = decoder.reorder_deadline();
+        crate::assert_with_log!(
+            deadline.is_none(),
+            "nothing left to time out",
+            None::<Time>,
+            deadline
+        );
+        crate::test_complete!("reorder_buffer_flushes_held_symbols_once_block_decodes");
+    }
+
+    #[test]
+    fn reorder_buffer_enforces_symbol_and_byte_bounds() {
+        init_test("reorder_buffer_enforces_symbol_and_byte_bounds");
+        let (decoder, _, symbols) = reorder_fixture(ObjectId::new_for_test(703));
+        let mut decoder = decoder.with_reordering(ReorderConfig {
+            max_symbols: 2,
+            ..ReorderConfig::default()
+        });
+        for esi in [1, 2] {
+            let released = decoder.feed_at(symbol_with_esi(&symbols, esi), Time::ZERO);
+            crate::assert_with_log!(released.is_empty(), "held", 0, released.len());
+        }
+        let released = decoder.feed_at(symbol_with_esi(&symbols, 3), Time::ZERO);
+        crate::assert_with_log!(released.len() == 3, "symbol bound flush", 3, released.len());
+        let stats = decoder.progress().reorder;
+        crate::assert_with_log!(
+            stats.flushed_capacity == 3 && stats.buffered_symbols == 0,
+            "symbol bound",
+            (3, 0),
+            (stats.flushed_capacity, stats.buffered_symbols)
+        );
+
+        let (decoder, _, symbols) = reorder_fixture(ObjectId::new_for_test(704));
+        let mut decoder = decoder.with_reordering(ReorderConfig {
+            max_bytes: 300,
+            ..ReorderConfig::default()
+        });
+        let released = decoder.feed_at(symbol_with_esi(&symbols, 1), Time::ZERO);
+        crate::assert_with_log!(released.is_empty(), "256 bytes held", 0, released.len());
+        let released = decoder.feed_at(symbol_with_esi(&symbols, 2), Time::ZERO);
+        crate::assert_with_log!(released.len() == 2, "byte bound flush", 2, released.len());
+        let stats = decoder.progress().reorder;
+        crate::assert_with_log!(
+            stats.buffered_bytes == 0,
+            "byte bound",
+            0,
+            stats.buffered_bytes
+        );
+        crate::test_complete!("reorder_buffer_enforces_symbol_and_byte_bounds");
+    }
+
+    #[test]
+    fn reorder_buffer_decodes_identical_bytes() {
+        init_test("reorder_buffer_decodes_identical_bytes");
+        let (mut plain, data, symbols) = reorder_fixture(ObjectId::new_for_test(705));
+        for esi in OVERTAKEN_ARRIVAL {
+            let _ = plain.feed(symbol_with_esi(&symbols, esi)).expect("feed");
+        }
+        let plain_data = plain.into_data().expect("unbuffered decode");
+
+        let (decoder, _, _) = reorder_fixture(ObjectId::new_for_test(705));
+        let mut decoder = decoder.with_reordering(ReorderConfig::default());
+        for esi in OVERTAKEN_ARRIVAL {
+            for result in decoder.feed_at(symbol_with_esi(&symbols, esi), Time::ZERO) {
+                let _ = result.expect("feed");
+            }
+        }
+        let buffered_data = decoder.into_data().expect("buffered decode");
+        crate::assert_with_log!(plain_data == data, "unbuffered bytes", data, plain_data);
+        crate::assert_with_log!(buffered_data == data, "buffered bytes", data, buffered_data);
+        crate::test_complete!("reorder_buffer_decodes_identical_bytes");
+    }
 }
@@ -0,0 +1,335 @@
+//! Bounded symbol reordering ahead of the decoding pipeline.
+//!
+//! Symbols that arrive ahead of their block's next expected ESI are held for a
+//! short while so the gap can fill. A block whose source symbols all arrive
+//! (even out of order) then completes through the source fast path instead of
+//! a RaptorQ elimination, which is what a repair symbol slipping in front of a
+//! late source symbol would otherwise trigger.
+//!
+//! The buffer is bounded three ways — held symbols, held bytes and hold time on
+//! the caller's virtual clock — and every bound releases symbols rather than
+//! dropping them. Released symbols always go to the decoder in
+//! `(sbn, esi)` order. The pipeline additionally flushes a block as soon as it
+//! could finish (see [`DecodingPipeline::feed_at`](super::DecodingPipeline::feed_at)).
+
+use crate::security::AuthenticatedSymbol;
+use crate::types::Time;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Number of buckets in [`ReorderStats::distance_histogram`].
+pub const REORDER_DISTANCE_BUCKETS: usize = 6;
+
+/// Bounds for the symbol reordering buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorderConfig {
+    /// Maximum symbols held across all blocks before everything is flushed.
+    pub max_symbols: usize,
+    /// Maximum payload bytes held across all blocks before everything is flushed.
+    pub max_bytes: usize,
+    /// Maximum time a symbol may be held before its block is flushed.
+    pub max_hold: Duration,
+}
+
+impl Default for ReorderConfig {
+    fn default() -> Self {
+        Self {
+            max_symbols: 64,
+            max_bytes: 256 * 1024,
+            max_hold: Duration::from_millis(5),
+        }
+    }
+}
+
+/// Why held symbols were released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FlushReason {
+    /// The gap in front of the held symbols filled.
+    InOrder,
+    /// The symbol or byte bound was exceeded.
+    Capacity,
+    /// A held symbol reached `max_hold`.
+    HoldTime,
+    /// The block could finish with what was already received and held.
+    Completion,
+    /// The caller drained the buffer explicitly.
+    Manual,
+    /// Memory pressure asked the buffer to shrink.
+    Pressure,
+}
+
+/// Reordering statistics, surfaced through
+/// [`DecodingProgress::reorder`](super::DecodingProgress::reorder).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReorderStats {
+    /// Symbols that were held at least once.
+    pub held: u64,
+    /// Symbols released immediately (in order, late, or for a finished block).
+    pub passed_through: u64,
+    /// Reorder distance (`esi - next_expected_esi`) per arriving symbol,
+    /// bucketed as `0`, `1`, `2..=3`, `4..=7`, `8..=15` and `16..`.
+    pub distance_histogram: [u64; REORDER_DISTANCE_BUCKETS],
+    /// Sum of hold times of every released held symbol.
+    pub total_hold: Duration,
+    /// Longest hold time of any released held symbol.
+    pub max_hold: Duration,
+    /// Held symbols released because the gap in front of them filled.
+    pub released_in_order: u64,
+    /// Held symbols released because the symbol or byte bound was exceeded.
+    pub flushed_capacity: u64,
+    /// Held symbols released because their block reached `max_hold`.
+    pub flushed_hold_time: u64,
+    /// Held symbols released because their block could finish.
+    pub flushed_completion: u64,
+    /// Held symbols released by an explicit drain.
+    pub flushed_manual: u64,
+    /// Held symbols released to give memory back under pressure.
+    pub flushed_pressure: u64,
+    /// Symbols currently held.
+    pub buffered_symbols: usize,
+    /// Payload bytes currently held.
+    pub buffered_bytes: usize,
+}
+
+/// Outcome of admitting one symbol.
+#[derive(Debug)]
+pub(crate) enum Admission {
+    /// Release this symbol now; it is in order or behind the window.
+    Release(AuthenticatedSymbol),
+    /// The symbol is held until its gap fills or a bound fires.
+    Held,
+    /// A symbol with the same `(sbn, esi)` is already held.
+    Duplicate,
+}
+
+#[derive(Debug)]
+struct HeldSymbol {
+    symbol: AuthenticatedSymbol,
+    arrived: Time,
+}
+
+#[derive(Debug, Default)]
+struct BlockWindow {
+    next_esi: u32,
+    held: BTreeMap<u32, HeldSymbol>,
+}
+
+impl BlockWindow {
+    fn oldest_arrival(&self) -> Option<Time> {
+        self.held.values().map(|held| held.arrived).min()
+    }
+}
+
+/// Per-block ESI reordering window with global symbol/byte/time bounds.
+#[derive(Debug)]
+pub(crate) struct ReorderBuffer {
+    config: ReorderConfig,
+    blocks: BTreeMap<u8, BlockWindow>,
+    stats: ReorderStats,
+}
+
+impl ReorderBuffer {
+    pub(crate) fn new(config: ReorderConfig) -> Self {
+        Self {
+            config,
+            blocks: BTreeMap::new(),
+            stats: ReorderStats::default(),
+        }
+    }
+
+    pub(crate) const fn stats(&self) -> ReorderStats {
+        self.stats
+    }
+
+    /// Admits an arriving symbol. Symbols at or behind the block's next
+    /// expected ESI are released immediately; symbols ahead of it are held.
+    pub(crate) fn admit(&mut self, symbol: AuthenticatedSymbol, now: Time) -> Admission {
+        let sbn = symbol.symbol().sbn();
+        let esi = symbol.symbol().esi();
+        let window = self.blocks.entry(sbn).or_default();
+        let distance = esi.saturating_sub(window.next_esi);
+        self.stats.distance_histogram[distance_bucket(distance)] += 1;
+
+        if distance == 0 {
+            if esi == window.next_esi {
+                window.next_esi = esi.saturating_add(1);
+            }
+            self.stats.passed_through += 1;
+            return Admission::Release(symbol);
+        }
+        if window.held.contains_key(&esi) {
+            return Admission::Duplicate;
+        }
+        self.stats.buffered_bytes += symbol.symbol().len();
+        self.stats.buffered_symbols += 1;
+        self.stats.held += 1;
+        window.held.insert(
+            esi,
+            HeldSymbol {
+                symbol,
+                arrived: now,
+            },
+        );
+        Admission::Held
+    }
+
+    /// Records a symbol that bypassed the window (its block already finished).
+    pub(crate) fn pass_through(&mut self) {
+        self.stats.passed_through += 1;
+    }
+
+    /// Returns true once the held symbols exceed either capacity bound.
+    pub(crate) const fn over_capacity(&self) -> bool {
+        self.stats.buffered_symbols > self.config.max_symbols
+            || self.stats.buffered_bytes > self.config.max_bytes
+    }
+
+    /// Number of held source symbols (`esi < k`) for `sbn`.
+    pub(crate) fn held_source_symbols(&self, sbn: u8, k: usize) -> usize {
+        let k = u32::try_from(k).unwrap_or(u32::MAX);
+        self.blocks
+            .get(&sbn)
+            .map_or(0, |window| window.held.range(..k).count())
+    }
+
+    /// Returns true if any symbol of `sbn` is held.
+    pub(crate) fn holds_block(&self, sbn: u8) -> bool {
+        self.blocks
+            .get(&sbn)
+            .is_some_and(|window| !window.held.is_empty())
+    }
+
+    /// Earliest time at which a held symbol reaches `max_hold`.
+    pub(crate) fn deadline(&self) -> Option<Time> {
+        let max_hold = duration_nanos(self.config.max_hold);
+        self.blocks
+            .values()
+            .filter_map(BlockWindow::oldest_arrival)
+            .min()
+            .map(|arrived| arrived.saturating_add_nanos(max_hold))
+    }
+
+    /// Releases held symbols of `sbn` that the advancing window has reached.
+    pub(crate) fn take_in_order(&mut self, sbn: u8, now: Time) -> Vec<AuthenticatedSymbol> {
+        let mut released = Vec::new();
+        let Some(window) = self.blocks.get_mut(&sbn) else {
+            return released;
+        };
+        while let Some(held) = window.held.remove(&window.next_esi) {
+            window.next_esi = window.next_esi.saturating_add(1);
+            released.push(held);
+        }
+        self.finish_release(released, now, FlushReason::InOrder)
+    }
+
+    /// Releases every held symbol of `sbn` in ESI order.
+    pub(crate) fn take_block(
+        &mut self,
+        sbn: u8,
+        now: Time,
+        reason: FlushReason,
+    ) -> Vec<AuthenticatedSymbol> {
+        let Some(window) = self.blocks.get_mut(&sbn) else {
+            return Vec::new();
+        };
+        let held = std::mem::take(&mut window.held);
+        if let Some(&last) = held.keys().next_back() {
+            window.next_esi = window.next_esi.max(last.saturating_add(1));
+        }
+        self.finish_release(held.into_values().collect(), now, reason)
+    }
+
+    /// Releases every held symbol in `(sbn, esi)` order.
+    pub(crate) fn take_all(&mut self, now: Time, reason: FlushReason) -> Vec<AuthenticatedSymbol> {
+        let sbns: Vec<u8> = self.blocks.keys().copied().collect();
+        sbns.into_iter()
+            .flat_map(|sbn| self.take_block(sbn, now, reason))
+            .collect()
+    }
+
+    /// Releases whole blocks, oldest hold first, until at least
+    /// `target_bytes` of held payload are released or nothing is held.
+    pub(crate) fn take_for_pressure(
+        &mut self,
+        now: Time,
+        target_bytes: usize,
+    ) -> Vec<AuthenticatedSymbol> {
+        let mut oldest: Vec<(Time, u8)> = self
+            .blocks
+            .iter()
+            .filter_map(|(&sbn, window)| window.oldest_arrival().map(|arrived| (arrived, sbn)))
+            .collect();
+        oldest.sort_unstable();
+        let mut released = Vec::new();
+        let mut released_bytes = 0;
+        for (_, sbn) in oldest {
+            if released_bytes >= target_bytes {
+                break;
+            }
+            let block = self.take_block(sbn, now, FlushReason::Pressure);
+            released_bytes += block
+                .iter()
+                .map(|symbol| symbol.symbol().len())
+                .sum::<usize>();
+            released.extend(block);
+        }
+        released
+    }
+
+    /// Releases every block whose oldest held symbol reached `max_hold`.
+    pub(crate) fn take_expired(&mut self, now: Time) -> Vec<AuthenticatedSymbol> {
+        let max_hold = duration_nanos(self.config.max_hold);
+        let expired: Vec<u8> = self
+            .blocks
+            .iter()
+            .filter(|(_, window)| {
+                window
+                    .oldest_arrival()
+                    .is_some_and(|arrived| now.duration_since(arrived) >= max_hold)
+            })
+            .map(|(&sbn, _)| sbn)
+            .collect();
+        expired
+            .into_iter()
+            .flat_map(|sbn| self.take_block(sbn, now, FlushReason::HoldTime))
+            .collect()
+    }
+
+    fn finish_release(
+        &mut self,
+        released: Vec<HeldSymbol>,
+        now: Time,
+        reason: FlushReason,
+    ) -> Vec<AuthenticatedSymbol> {
+        let count = released.len() as u64;
+        let counter = match reason {
+            FlushReason::InOrder => &mut self.stats.released_in_order,
+            FlushReason::Capacity => &mut self.stats.flushed_capacity,
+            FlushReason::HoldTime => &mut self.stats.flushed_hold_time,
+            FlushReason::Completion => &mut self.stats.flushed_completion,
+            FlushReason::Manual => &mut self.stats.flushed_manual,
+            FlushReason::Pressure => &mut self.stats.flushed_pressure,
+        };
+        *counter += count;
+        released
+            .into_iter()
+            .map(|held| {
+                let hold = Duration::from_nanos(now.duration_since(held.arrived));
+                self.stats.total_hold = self.stats.total_hold.saturating_add(hold);
+                self.stats.max_hold = self.stats.max_hold.max(hold);
+                self.stats.buffered_symbols -= 1;
+                self.stats.buffered_bytes -= held.symbol.symbol().len();
+                held.symbol
+            })
+            .collect()
+    }
+}
+
+fn distance_bucket(distance: u32) -> usize {
+    let bits = (u32::BITS - distance.leading_zeros()) as usize;
+    bits.min(REORDER_DISTANCE_BUCKETS - 1)
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,532 @@
+//! Peer identity and authorization for inbound remote traffic.
+//!
+//! Reaching a node's transport port is not permission to use it. Once a
+//! connection is authenticated (TLS client certificate, token, or only the
+//! shared cluster key in degenerate setups) and its [session](super::session)
+//! is established, the transport builds a [`PeerIdentity`] and asks the
+//! [`PeerGate`] to admit it. The gate consults the user's [`Authorizer`]
+//! twice:
+//!
+//! - [`Authorizer::authorize_connection`] when the peer connects. A denial
+//!   closes the connection before any message from it is processed.
+//! - [`Authorizer::authorize_capability`] for every inbound message, keyed
+//!   by the [`RemoteCapability`] the message exercises. A denial rejects
+//!   that message (a spawn is answered with
+//!   [`SpawnRejectReason::Unauthorized`]) and leaves the connection up, so a
+//!   policy can refuse spawns from a peer while still accepting its lease
+//!   heartbeats.
+//!
+//! Every connection decision and every capability denial is recorded as an
+//! [`AuthzAuditEvent`] carrying the peer identity and the reason; allowed
+//! capability checks are not recorded. Drain them with
+//! [`PeerGate::drain_audit`].
+//!
+//! [`PeerGate::default`] uses [`AllowAll`], which keeps the behavior of
+//! transports that predate authorization, and warns in release builds that
+//! no authorizer was configured.
+//!
+//! [`SpawnRejectReason::Unauthorized`]: crate::remote::SpawnRejectReason::Unauthorized
+
+use super::session::EstablishedSession;
+use crate::remote::{ComputationName, MessageEnvelope, NodeId, RemoteMessage};
+use crate::tracing_compat::{info, warn};
+use crate::types::Time;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// What a peer authenticated with. Never holds secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PeerCredentials {
+    /// A TLS client certificate.
+    ClientCertificate {
+        /// Certificate subject.
+        subject: String,
+        /// SHA-256 fingerprint of the DER certificate.
+        fingerprint: [u8; 32],
+    },
+    /// A bearer token, identified by its id.
+    Token {
+        /// Token identifier.
+        id: String,
+    },
+    /// Only the shared cluster key: proves membership, not which member.
+    ClusterKey,
+}
+
+impl PeerCredentials {
+    /// Returns a short label for the credential type.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::ClientCertificate { .. } => "client_certificate",
+            Self::Token { .. } => "token",
+            Self::ClusterKey => "cluster_key",
+        }
+    }
+}
+
+impl fmt::Display for PeerCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClientCertificate {
+                subject,
+                fingerprint,
+            } => {
+                write!(f, "certificate {subject} sha256:")?;
+                for byte in &fingerprint[..8] {
+                    write!(f, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+            Self::Token { id } => write!(f, "token {id}"),
+            Self::ClusterKey => f.write_str("cluster key"),
+        }
+    }
+}
+
+/// An authenticated peer, as presented to an [`Authorizer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerIdentity {
+    /// The peer's node id.
+    pub node: NodeId,
+    /// What the peer authenticated with.
+    pub credentials: PeerCredentials,
+    /// Schema versions negotiated with the peer.
+    pub schema_versions: BTreeMap<String, u32>,
+}
+
+impl PeerIdentity {
+    /// Creates an identity with no negotiated schemas.
+    #[must_use]
+    pub const fn new(node: NodeId, credentials: PeerCredentials) -> Self {
+        Self {
+            node,
+            credentials,
+            schema_versions: BTreeMap::new(),
+        }
+    }
+
+    /// Creates the identity of a peer known only by the cluster key.
+    #[must_use]
+    pub const fn cluster_member(node: NodeId) -> Self {
+        Self::new(node, PeerCredentials::ClusterKey)
+    }
+
+    /// Creates the identity of the peer of an established session.
+    #[must_use]
+    pub fn from_session(session: &EstablishedSession, credentials: PeerCredentials) -> Self {
+        Self {
+            node: session.peer.clone(),
+            credentials,
+            schema_versions: session.schema_versions.clone(),
+        }
+    }
+
+    /// Adds a negotiated schema version.
+    #[must_use]
+    pub fn with_schema(mut self, schema: impl Into<String>, version: u32) -> Self {
+        self.schema_versions.insert(schema.into(), version);
+        self
+    }
+
+    /// Returns the negotiated version of `schema`.
+    #[must_use]
+    pub fn schema_version(&self, schema: &str) -> Option<u32> {
+        self.schema_versions.get(schema).copied()
+    }
+}
+
+impl fmt::Display for PeerIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.node, self.credentials)
+    }
+}
+
+/// What an inbound remote message asks the receiving node to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteCapability {
+    /// Run a computation ([`RemoteMessage::SpawnRequest`]).
+    Spawn(ComputationName),
+    /// Cancel a task ([`RemoteMessage::CancelRequest`]).
+    Cancel,
+    /// Answer a request this node made ([`RemoteMessage::SpawnAck`],
+    /// [`RemoteMessage::ResultDelivery`]).
+    Respond,
+    /// Renew or grant a lease ([`RemoteMessage::LeaseRenewal`]).
+    Heartbeat,
+}
+
+impl RemoteCapability {
+    /// Returns the capability `message` exercises.
+    #[must_use]
+    pub fn of(message: &RemoteMessage) -> Self {
+        match message {
+            RemoteMessage::SpawnRequest(request) => Self::Spawn(request.computation.clone()),
+            RemoteMessage::CancelRequest(_) => Self::Cancel,
+            RemoteMessage::SpawnAck(_) | RemoteMessage::ResultDelivery(_) => Self::Respond,
+            RemoteMessage::LeaseRenewal(_) => Self::Heartbeat,
+        }
+    }
+
+    /// Returns a short label for the capability.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Spawn(_) => "spawn",
+            Self::Cancel => "cancel",
+            Self::Respond => "respond",
+            Self::Heartbeat => "heartbeat",
+        }
+    }
+}
+
+impl fmt::Display for RemoteCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spawn(computation) => write!(f, "spawn {computation}"),
+            other => f.write_str(other.kind()),
+        }
+    }
+}
+
+/// An authorization denial.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("authorization denied: {reason}")]
+pub struct AuthzDenied {
+    /// Why the request was denied.
+    pub reason: String,
+}
+
+impl AuthzDenied {
+    /// Creates a denial.
+    #[must_use]
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Decides what authenticated peers may do.
+pub trait Authorizer: Send + Sync + fmt::Debug {
+    /// Decides whether `peer` may connect at all.
+    fn authorize_connection(&self, peer: &PeerIdentity) -> Result<(), AuthzDenied>;
+
+    /// Decides whether a connected `peer` may exercise `capability`.
+    fn authorize_capability(
+        &self,
+        peer: &PeerIdentity,
+        capability: &RemoteCapability,
+    ) -> Result<(), AuthzDenied>;
+}
+
+/// Authorizer that allows everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+impl Authorizer for AllowAll {
+    fn authorize_connection(&self, _peer: &PeerIdentity) -> Result<(), AuthzDenied> {
+        Ok(())
+    }
+
+    fn authorize_capability(
+        &self,
+        _peer: &PeerIdentity,
+        _capability: &RemoteCapability,
+    ) -> Result<(), AuthzDenied> {
+        Ok(())
+    }
+}
+
+/// What an audited decision was about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthzSubject {
+    /// Admitting the connection.
+    Connection,
+    /// Exercising a capability.
+    Capability(RemoteCapability),
+}
+
+/// An audited authorization decision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthzAuditEvent {
+    /// When the decision was made.
+    pub at: Time,
+    /// The peer the decision was about.
+    pub peer: PeerIdentity,
+    /// What was decided.
+    pub subject: AuthzSubject,
+    /// `None` if allowed, otherwise the reason for the denial.
+    pub denied: Option<String>,
+}
+
+/// Admits connections and checks inbound messages against an
+/// [`Authorizer`]. See the [module docs](self).
+#[derive(Debug)]
+pub struct PeerGate {
+    authorizer: Arc<dyn Authorizer>,
+    peers: BTreeMap<NodeId, PeerIdentity>,
+    audit: Vec<AuthzAuditEvent>,
+}
+
+impl Default for PeerGate {
+    fn default() -> Self {
+        #[cfg(not(debug_assertions))]
+        warn!("no remote authorizer configured; every authenticated peer may spawn remote work");
+        Self::new(Arc::new(AllowAll))
+    }
+}
+
+impl PeerGate {
+    /// Creates a gate with no admitted peers.
+    #[must_use]
+    pub fn new(authorizer: Arc<dyn Authorizer>) -> Self {
+        Self {
+            authorizer,
+            peers: BTreeMap::new(),
+            audit: Vec::new(),
+        }
+    }
+
+    /// Asks the authorizer whether `peer` may connect. On success the
+    /// identity is kept for the connection's lifetime, replacing any earlier
+    /// connection from the same node; on denial the caller must close the
+    /// connection without processing anything it sent.
+    pub fn admit(&mut self, peer: PeerIdentity, now: Time) -> Result<(), AuthzDenied> {
+        let decision = self.authorizer.authorize_connection(&peer);
+        let denied = decision.as_ref().err().map(|denied| denied.reason.clone());
+        if denied.is_some() {
+            warn!(peer = %peer, reason = ?denied, "remote peer refused");
+        } else {
+            info!(peer = %peer, "remote peer admitted");
+        }
+        self.audit.push(AuthzAuditEvent {
+            at: now,
+            peer: peer.clone(),
+            subject: AuthzSubject::Connection,
+            denied,
+        });
+        if decision.is_ok() {
+            self.peers.insert(peer.node.clone(), peer);
+        } else {
+            self.peers.remove(&peer.node);
+        }
+        decision
+    }
+
+    /// Forgets the connection from `node`, returning its identity.
+    pub fn disconnect(&mut self, node: &NodeId) -> Option<PeerIdentity> {
+        self.peers.remove(node)
+    }
+
+    /// Forgets every connection.
+    pub fn disconnect_all(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Returns the identity `node` was admitted with.
+    #[must_use]
+    pub fn peer(&self, node: &NodeId) -> Option<&PeerIdentity> {
+        self.peers.get(node)
+    }
+
+    /// Returns true if `node` has an admitted connection.
+    #[must_use]
+    pub fn is_admitted(&self, node: &NodeId) -> bool {
+        self.peers.contains_key(node)
+    }
+
+    /// Asks the authorizer whether `node` may exercise `capability`. Peers
+    /// without an admitted connection are denied without asking.
+    pub fn authorize(
+        &mut self,
+        node: &NodeId,
+        capability: &RemoteCapability,
+        now: Time,
+    ) -> Result<(), AuthzDenied> {
+        let Some(peer) = self.peers.get(node) else {
+            return Err(AuthzDenied::new("no admitted connection"));
+        };
+        let decision = self.authorizer.authorize_capability(peer, capability);
+        if let Err(denied) = &decision {
+            warn!(
+                peer = %peer,
+                capability = %capability,
+                reason = %denied.reason,
+                "remote capability denied"
+            );
+            self.audit.push(AuthzAuditEvent {
+                at: now,
+                peer: peer.clone(),
+                subject: AuthzSubject::Capability(capability.clone()),
+                denied: Some(denied.reason.clone()),
+            });
+        }
+        decision
+    }
+
+    /// Checks the capability an inbound message exercises against its
+    /// sender's connection.
+    pub fn check(
+        &mut self,
+        envelope: &MessageEnvelope<RemoteMessage>,
+        now: Time,
+    ) -> Result<(), AuthzDenied> {
+        let capability = RemoteCapability::of(&envelope.payload);
+        self.authorize(&envelope.sender, &capability, now)
+    }
+
+    /// Returns the audit events recorded since the last drain.
+    #[must_use]
+    pub fn audit(&self) -> &[AuthzAuditEvent] {
+        &self.audit
+    }
+
+    /// Takes the audit events recorded since the last drain.
+    pub fn drain_audit(&mut self) -> Vec<AuthzAuditEvent> {
+        std::mem::take(&mut self.audit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::distributed::session::{
+        Initiator, InitiatorStep, Responder, ResponderStep, SchemaRange, SessionOffer,
+    };
+    use crate::security::AuthKey;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Admits certificate holders only; token holders may heartbeat but
+    /// not spawn.
+    #[derive(Debug)]
+    struct CertificatesOnly;
+
+    impl Authorizer for CertificatesOnly {
+        fn authorize_connection(&self, peer: &PeerIdentity) -> Result<(), AuthzDenied> {
+            match peer.credentials {
+                PeerCredentials::ClusterKey => Err(AuthzDenied::new("cluster key alone")),
+                _ => Ok(()),
+            }
+        }
+
+        fn authorize_capability(
+            &self,
+            peer: &PeerIdentity,
+            capability: &RemoteCapability,
+        ) -> Result<(), AuthzDenied> {
+            match (&peer.credentials, capability) {
+                (PeerCredentials::Token { .. }, RemoteCapability::Spawn(_)) => {
+                    Err(AuthzDenied::new("tokens may not spawn"))
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn establish(responder_node: &str, initiator_node: &str) -> EstablishedSession {
+        let key = AuthKey::from_seed(7);
+        let schema = SchemaRange::new(1, 3);
+        let local = SessionOffer::new(NodeId::new(responder_node)).with_schema("spawn", schema);
+        let remote = SessionOffer::new(NodeId::new(initiator_node))
+            .with_schema("spawn", SchemaRange::new(2, 5));
+        let mut initiator = Initiator::new(remote, key, 1);
+        let mut responder = Responder::new(local, key, 2);
+        let mut message = initiator.start();
+        loop {
+            match responder.handle(message, Time::ZERO, None).expect("responder") {
+                ResponderStep::Reply(reply) => match initiator.handle(reply).expect("initiator") {
+                    InitiatorStep::Send(next) => message = next,
+                    InitiatorStep::Established(_) => unreachable!("responder finishes first"),
+                },
+                ResponderStep::Established { session, .. } => return session,
+            }
+        }
+    }
+
+    #[test]
+    fn identity_carries_session_parameters() {
+        init_test("identity_carries_session_parameters");
+        let session = establish("worker", "scheduler");
+        let credentials = PeerCredentials::Token {
+            id: "deploy-bot".into(),
+        };
+        let peer = PeerIdentity::from_session(&session, credentials);
+        crate::assert_with_log!(
+            peer.node == NodeId::new("scheduler"),
+            "node",
+            "scheduler",
+            peer.node
+        );
+        let version = peer.schema_version("spawn");
+        crate::assert_with_log!(version == Some(3), "negotiated schema", Some(3), version);
+        let shown = peer.to_string();
+        crate::assert_with_log!(
+            shown == "Node(scheduler) (token deploy-bot)",
+            "display",
+            "Node(scheduler) (token deploy-bot)",
+            shown
+        );
+        crate::test_complete!("identity_carries_session_parameters");
+    }
+
+    #[test]
+    fn audit_events_carry_identity_and_reason() {
+        init_test("audit_events_carry_identity_and_reason");
+        let mut gate = PeerGate::new(Arc::new(CertificatesOnly));
+        let anonymous = PeerIdentity::cluster_member(NodeId::new("stray"));
+        let refused = gate.admit(anonymous.clone(), Time::from_secs(1));
+        crate::assert_with_log!(refused.is_err(), "cluster key refused", true, refused);
+        let admitted = gate.is_admitted(&anonymous.node);
+        crate::assert_with_log!(!admitted, "not admitted", false, admitted);
+
+        let bot = PeerIdentity::new(NodeId::new("bot"), PeerCredentials::Token { id: "ci".into() });
+        gate.admit(bot.clone(), Time::from_secs(2)).expect("token admitted");
+        let spawn = RemoteCapability::Spawn(ComputationName::new("resize"));
+        let denied = gate.authorize(&bot.node, &spawn, Time::from_secs(3));
+        crate::assert_with_log!(denied.is_err(), "spawn denied", true, denied);
+        let heartbeat = gate.authorize(&bot.node, &RemoteCapability::Heartbeat, Time::from_secs(4));
+        crate::assert_with_log!(heartbeat.is_ok(), "heartbeat allowed", true, heartbeat);
+        let stray = gate.authorize(&anonymous.node, &RemoteCapability::Heartbeat, Time::ZERO);
+        crate::assert_with_log!(stray.is_err(), "unadmitted denied", true, stray);
+
+        let audit = gate.drain_audit();
+        let expected = vec![
+            AuthzAuditEvent {
+                at: Time::from_secs(1),
+                peer: anonymous,
+                subject: AuthzSubject::Connection,
+                denied: Some("cluster key alone".into()),
+            },
+            AuthzAuditEvent {
+                at: Time::from_secs(2),
+                peer: bot.clone(),
+                subject: AuthzSubject::Connection,
+                denied: None,
+            },
+            AuthzAuditEvent {
+                at: Time::from_secs(3),
+                peer: bot,
+                subject: AuthzSubject::Capability(spawn),
+                denied: Some("tokens may not spawn".into()),
+            },
+        ];
+        crate::assert_with_log!(audit == expected, "audit trail", expected, audit);
+        crate::assert_with_log!(gate.audit().is_empty(), "drained", 0, gate.audit().len());
+        crate::test_complete!("audit_events_carry_identity_and_reason");
+    }
+}
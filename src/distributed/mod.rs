@@ -21,10 +21,14 @@
 //! - [`bridge`]: Local-to-distributed region bridge
 //! - [`consensus`]: Byzantine fault tolerant consensus algorithms
 //! - [`membership`]: SWIM-style cluster membership and failure detection
+//! - [`session`]: Session handshake with resumption tickets
+//! - [`authz`]: Peer identity and authorization of inbound remote traffic
+//! - [`replication`]: Single-leader replicated log over a user state machine
 
 pub mod adaptive_layout;
 pub mod anti_entropy;
 pub mod assignment;
+pub mod authz;
 pub mod bridge;
 pub mod computation_schema;
 pub mod consensus;
@@ -33,11 +37,17 @@ pub mod distribution;
 pub mod encoding;
 pub mod membership;
 pub mod recovery;
+pub mod replication;
+pub mod session;
 pub mod snapshot;
 
 pub use adaptive_layout::{AdaptiveLayoutConfig, BlockLayoutChoice, PathQuality};
 pub use anti_entropy::{DiffKind, DiffReport, KeyDiff, MerkleRangeTree};
 pub use assignment::{AssignmentStrategy, ReplicaAssignment, SymbolAssigner};
+pub use authz::{
+    AllowAll, Authorizer, AuthzAuditEvent, AuthzDenied, AuthzSubject, PeerCredentials, PeerGate,
+    PeerIdentity, RemoteCapability,
+};
 pub use bridge::{
     BridgeConfig, CloseResult, ConflictResolution, DistributedToLocal, EffectiveState,
     LocalToDistributed, RegionBridge, RegionMode, SyncMode, SyncResult, SyncState, UpgradeResult,
@@ -68,6 +78,14 @@ pub use recovery::{
     RecoveryDecodingConfig, RecoveryOrchestrator, RecoveryPhase, RecoveryProgress, RecoveryResult,
     RecoveryTrigger, StateDecoder,
 };
+pub use replication::{
+    ReplicatedLog, ReplicationConfig, ReplicationError, ReplicationMessage, StateMachine,
+};
+pub use session::{
+    EstablishedSession, FallbackReason, HandshakeError, HandshakeMessage, Initiator, InitiatorStep,
+    IssuedTicket, Responder, ResponderStep, ResumeRejection, ResumptionTicket, SchemaRange,
+    SessionEvent, SessionOffer, StoredTicket, TicketAuthority, TicketPolicy,
+};
 pub use snapshot::{BudgetSnapshot, RegionSnapshot, SnapshotError, TaskSnapshot, TaskState};
 
 #[cfg(test)]
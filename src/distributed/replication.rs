@@ -0,0 +1,1640 @@
+//! Single-leader state machine replication over a fixed member set.
+//!
+//! [`ReplicatedLog`] sequences commands through one elected leader, ships them
+//! to followers, and applies a command to the user's [`StateMachine`] only
+//! after a majority of members has acknowledged it. It is deliberately
+//! smaller than Raft: membership is fixed at construction and there is no
+//! joint consensus. The safety property it keeps is Raft's: no two replicas
+//! ever apply different commands at the same log index.
+//!
+//! # Leadership
+//!
+//! Leadership is a lease. A vote is also a [`LeaseRenewal`] grant and every
+//! accepted append renews it. A member holding an unexpired grant refuses to
+//! vote for anyone else, and a leader steps down as soon as it no longer holds
+//! grants from a quorum; [`HeldLease`] discounts link delay and clock skew on
+//! the leader's side, so the leader's view always expires first. Terms and
+//! the up-to-date vote check carry the log safety argument, while the lease
+//! keeps a deposed leader from accepting writes on stale authority.
+//!
+//! A new leader appends a barrier entry for its term. Once that commits,
+//! every entry before it is committed too, so failover replays from the last
+//! quorum-acknowledged index using whatever the surviving followers hold.
+//!
+//! # Driving a replica
+//!
+//! The core is sans-IO. [`ReplicatedLog::step`], [`ReplicatedLog::tick`] and
+//! [`ReplicatedLog::propose`] queue [`Envelope`]s that the caller carries over
+//! its transport and collects with [`ReplicatedLog::take_outbound`]; applied
+//! commands and their outputs come back from [`ReplicatedLog::take_applied`].
+//! Lost, duplicated and reordered messages are tolerated.
+//!
+//! # Snapshots
+//!
+//! Once `snapshot_threshold` entries have been applied past the last
+//! snapshot, the state machine is snapshotted and the log truncated. A
+//! follower that falls behind the truncation point is caught up with
+//! [`ReplicationMessage::InstallSnapshot`].
+
+use crate::remote::{
+    ClockSkewConfig, HeldLease, LeaseRenewal, NodeId, PeerClock, RemoteTaskId, RemoteTaskState,
+};
+use crate::types::Time;
+use crate::util::DetRng;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
+
+/// A command that can be replicated.
+///
+/// Blanket-implemented for every cloneable, debuggable, sendable type.
+pub trait Command: Clone + fmt::Debug + Send + 'static {}
+
+impl<T: Clone + fmt::Debug + Send + 'static> Command for T {}
+
+/// The deterministic state machine a [`ReplicatedLog`] drives.
+///
+/// Every replica applies the same commands in the same order, so `apply` must
+/// depend only on the current state and the command.
+pub trait StateMachine<C: Command> {
+    /// Result of applying one command, handed back through
+    /// [`ReplicatedLog::take_applied`].
+    type Output;
+
+    /// Applies the committed command at `index`.
+    fn apply(&mut self, index: u64, command: &C) -> Self::Output;
+
+    /// Serializes the state reached by the last applied command.
+    fn snapshot(&self) -> Vec<u8>;
+
+    /// Replaces the state with one produced by [`snapshot`](Self::snapshot).
+    fn restore(&mut self, snapshot: &[u8]) -> Result<(), String>;
+}
+
+/// Errors returned by [`ReplicatedLog`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReplicationError {
+    /// The configuration is inconsistent.
+    #[error("invalid replication config: {0}")]
+    InvalidConfig(&'static str),
+    /// Commands can only be proposed on a leader holding its lease.
+    #[error("not the leader (last known leader: {leader:?})")]
+    NotLeader {
+        /// The leader this replica last heard from, if any.
+        leader: Option<NodeId>,
+    },
+}
+
+/// Replica role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Accepting entries from a leader.
+    Follower,
+    /// Soliciting votes for a new term.
+    Candidate,
+    /// Sequencing commands for the current term.
+    Leader,
+}
+
+/// Configuration for one replica.
+#[derive(Debug, Clone)]
+pub struct ReplicationConfig {
+    /// This replica.
+    pub node: NodeId,
+    /// Every member, including `node`. Fixed for the life of the log.
+    pub members: Vec<NodeId>,
+    /// Minimum silence before a follower starts an election. The actual
+    /// timeout is drawn from `[election_timeout, 2 * election_timeout)`.
+    pub election_timeout: Duration,
+    /// Interval between leader appends when there is nothing new to send.
+    pub heartbeat_interval: Duration,
+    /// Leadership lease granted by each vote and renewed by each append.
+    pub lease_duration: Duration,
+    /// Applied entries past the last snapshot that trigger a new one; zero
+    /// disables snapshots.
+    pub snapshot_threshold: u64,
+    /// Most entries carried by one append.
+    pub max_batch: usize,
+    /// Skew tolerance for the lease timing exchange.
+    pub clock_skew: ClockSkewConfig,
+    /// Seed for the randomized election timeout.
+    pub seed: u64,
+}
+
+impl ReplicationConfig {
+    /// Creates a configuration with default timing for `node` in `members`.
+    #[must_use]
+    pub fn new(node: NodeId, members: impl IntoIterator<Item = NodeId>) -> Self {
+        Self {
+            node,
+            members: members.into_iter().collect(),
+            election_timeout: Duration::from_millis(300),
+            heartbeat_interval: Duration::from_millis(50),
+            lease_duration: Duration::from_millis(200),
+            snapshot_threshold: 1024,
+            max_batch: 64,
+            clock_skew: ClockSkewConfig::default(),
+            seed: 0,
+        }
+    }
+
+    /// Sets the election timeout.
+    #[must_use]
+    pub const fn with_election_timeout(mut self, timeout: Duration) -> Self {
+        self.election_timeout = timeout;
+        self
+    }
+
+    /// Sets the heartbeat interval.
+    #[must_use]
+    pub const fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// Sets the leadership lease duration.
+    #[must_use]
+    pub const fn with_lease_duration(mut self, lease: Duration) -> Self {
+        self.lease_duration = lease;
+        self
+    }
+
+    /// Sets the snapshot threshold.
+    #[must_use]
+    pub const fn with_snapshot_threshold(mut self, threshold: u64) -> Self {
+        self.snapshot_threshold = threshold;
+        self
+    }
+
+    /// Sets the append batch size.
+    #[must_use]
+    pub const fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch;
+        self
+    }
+
+    /// Sets the election timeout seed.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Checks the configuration.
+    ///
+    /// The lease must fit inside the election timeout: a follower refuses
+    /// votes until the lease it granted runs out, so a longer lease would
+    /// stall every election after a leader failure.
+    pub fn validate(&self) -> Result<(), ReplicationError> {
+        if !self.members.contains(&self.node) {
+            return Err(ReplicationError::InvalidConfig("members must include node"));
+        }
+        let distinct: BTreeSet<&NodeId> = self.members.iter().collect();
+        if distinct.len() != self.members.len() {
+            return Err(ReplicationError::InvalidConfig("duplicate member"));
+        }
+        if self.heartbeat_interval.is_zero() || self.heartbeat_interval >= self.lease_duration {
+            return Err(ReplicationError::InvalidConfig(
+                "heartbeat_interval must be non-zero and shorter than lease_duration",
+            ));
+        }
+        if self.lease_duration > self.election_timeout {
+            return Err(ReplicationError::InvalidConfig(
+                "lease_duration must not exceed election_timeout",
+            ));
+        }
+        if self.max_batch == 0 {
+            return Err(ReplicationError::InvalidConfig("max_batch must be non-zero"));
+        }
+        Ok(())
+    }
+}
+
+/// One log slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry<C> {
+    /// Term of the leader that created the entry.
+    pub term: u64,
+    /// Position in the log, starting at 1.
+    pub index: u64,
+    /// The command, or `None` for the barrier a new leader appends.
+    pub command: Option<C>,
+}
+
+/// Protocol messages exchanged between replicas.
+#[derive(Debug, Clone)]
+pub enum ReplicationMessage<C> {
+    /// A candidate asks for a vote and a leadership lease.
+    RequestVote {
+        /// The candidate's term.
+        term: u64,
+        /// Index of the candidate's last entry.
+        last_log_index: u64,
+        /// Term of the candidate's last entry.
+        last_log_term: u64,
+        /// Lease request, stamped for the delay measurement.
+        lease: LeaseRenewal,
+    },
+    /// Reply to [`RequestVote`](Self::RequestVote).
+    Vote {
+        /// The voter's term.
+        term: u64,
+        /// Whether the vote was granted.
+        granted: bool,
+        /// The lease grant accompanying a granted vote.
+        lease: Option<LeaseRenewal>,
+    },
+    /// The leader replicates entries, or heartbeats with none.
+    Append {
+        /// The leader's term.
+        term: u64,
+        /// Index of the entry preceding `entries`.
+        prev_index: u64,
+        /// Term of the entry at `prev_index`.
+        prev_term: u64,
+        /// Entries starting at `prev_index + 1`.
+        entries: Vec<LogEntry<C>>,
+        /// The leader's commit index.
+        leader_commit: u64,
+        /// Lease renewal request.
+        lease: LeaseRenewal,
+    },
+    /// Reply to [`Append`](Self::Append) and
+    /// [`InstallSnapshot`](Self::InstallSnapshot).
+    AppendAck {
+        /// The follower's term.
+        term: u64,
+        /// Whether the entries were accepted.
+        success: bool,
+        /// On success, the last index known to match the leader; otherwise
+        /// a hint for where the leader should retry.
+        match_index: u64,
+        /// Lease renewal, present when the follower accepted the leader.
+        lease: Option<LeaseRenewal>,
+    },
+    /// The leader ships its snapshot to a follower behind the truncation
+    /// point.
+    InstallSnapshot {
+        /// The leader's term.
+        term: u64,
+        /// Last index covered by the snapshot.
+        last_index: u64,
+        /// Term of the entry at `last_index`.
+        last_term: u64,
+        /// Output of [`StateMachine::snapshot`].
+        data: Vec<u8>,
+        /// Lease renewal request.
+        lease: LeaseRenewal,
+    },
+}
+
+/// A message addressed from one replica to another.
+#[derive(Debug, Clone)]
+pub struct Envelope<C> {
+    /// Sending replica.
+    pub from: NodeId,
+    /// Receiving replica.
+    pub to: NodeId,
+    /// The message.
+    pub message: ReplicationMessage<C>,
+}
+
+/// A committed command and the state machine's output for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Applied<C, O> {
+    /// Log index of the command.
+    pub index: u64,
+    /// Term in which the command was proposed.
+    pub term: u64,
+    /// The command.
+    pub command: C,
+    /// What [`StateMachine::apply`] returned.
+    pub output: O,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Progress {
+    /// Next index to send; advanced optimistically as appends go out.
+    next: u64,
+    /// Highest index acknowledged as matching.
+    matched: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    index: u64,
+    term: u64,
+    data: Vec<u8>,
+}
+
+/// One replica of a single-leader replicated log.
+pub struct ReplicatedLog<C: Command, S: StateMachine<C>> {
+    config: ReplicationConfig,
+    peers: Vec<NodeId>,
+    machine: S,
+    role: Role,
+    term: u64,
+    voted_for: Option<NodeId>,
+    leader: Option<NodeId>,
+    /// Entries after `snapshot.index`.
+    log: Vec<LogEntry<C>>,
+    snapshot: Snapshot,
+    commit_index: u64,
+    last_applied: u64,
+    election_deadline: Time,
+    next_heartbeat: Time,
+    votes: BTreeSet<NodeId>,
+    progress: BTreeMap<NodeId, Progress>,
+    /// Leadership leases held from each peer for the current term.
+    held: BTreeMap<NodeId, HeldLease>,
+    /// The lease this replica last granted and its local expiry.
+    granted: Option<(NodeId, Time)>,
+    /// Grantor-side clock views, used to stamp grants.
+    peer_clocks: BTreeMap<NodeId, PeerClock>,
+    rng: DetRng,
+    outbound: Vec<Envelope<C>>,
+    applied: Vec<Applied<C, S::Output>>,
+}
+
+impl<C: Command, S: StateMachine<C>> fmt::Debug for ReplicatedLog<C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicatedLog")
+            .field("node", &self.config.node)
+            .field("role", &self.role)
+            .field("term", &self.term)
+            .field("leader", &self.leader)
+            .field("commit_index", &self.commit_index)
+            .field("last_applied", &self.last_applied)
+            .field("snapshot_index", &self.snapshot.index)
+            .field("last_index", &self.last_index())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Command, S: StateMachine<C>> ReplicatedLog<C, S> {
+    /// Creates a follower at term zero with an empty log.
+    pub fn new(config: ReplicationConfig, machine: S, now: Time) -> Result<Self, ReplicationError> {
+        config.validate()?;
+        let peers = config
+            .members
+            .iter()
+            .filter(|member| **member != config.node)
+            .cloned()
+            .collect();
+        let rng = DetRng::new(config.seed);
+        let mut log = Self {
+            config,
+            peers,
+            machine,
+            role: Role::Follower,
+            term: 0,
+            voted_for: None,
+            leader: None,
+            log: Vec::new(),
+            snapshot: Snapshot::default(),
+            commit_index: 0,
+            last_applied: 0,
+            election_deadline: now,
+            next_heartbeat: now,
+            votes: BTreeSet::new(),
+            progress: BTreeMap::new(),
+            held: BTreeMap::new(),
+            granted: None,
+            peer_clocks: BTreeMap::new(),
+            rng,
+            outbound: Vec::new(),
+            applied: Vec::new(),
+        };
+        log.reset_election_deadline(now);
+        Ok(log)
+    }
+
+    /// Returns this replica's node ID.
+    #[must_use]
+    pub fn id(&self) -> &NodeId {
+        &self.config.node
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &ReplicationConfig {
+        &self.config
+    }
+
+    /// Returns the current role.
+    #[must_use]
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Returns the current term.
+    #[must_use]
+    pub fn term(&self) -> u64 {
+        self.term
+    }
+
+    /// Returns the leader this replica last heard from.
+    #[must_use]
+    pub fn leader(&self) -> Option<&NodeId> {
+        self.leader.as_ref()
+    }
+
+    /// Returns true if this replica is the leader and holds leases from a
+    /// quorum at local time `now`.
+    #[must_use]
+    pub fn is_leader(&self, now: Time) -> bool {
+        if self.role != Role::Leader {
+            return false;
+        }
+        let held = self
+            .held
+            .values()
+            .filter(|lease| lease.is_held(now))
+            .count();
+        held + 1 >= self.quorum()
+    }
+
+    /// Returns the highest index known to be committed.
+    #[must_use]
+    pub fn commit_index(&self) -> u64 {
+        self.commit_index
+    }
+
+    /// Returns the highest index applied to the state machine.
+    #[must_use]
+    pub fn last_applied(&self) -> u64 {
+        self.last_applied
+    }
+
+    /// Returns the index of the last log entry.
+    #[must_use]
+    pub fn last_index(&self) -> u64 {
+        self.snapshot.index + self.log.len() as u64
+    }
+
+    /// Returns the last index covered by the current snapshot, zero if none.
+    #[must_use]
+    pub fn snapshot_index(&self) -> u64 {
+        self.snapshot.index
+    }
+
+    /// Returns the entries still held in the log, after the snapshot.
+    #[must_use]
+    pub fn entries(&self) -> &[LogEntry<C>] {
+        &self.log
+    }
+
+    /// Returns the state machine.
+    #[must_use]
+    pub fn state_machine(&self) -> &S {
+        &self.machine
+    }
+
+    /// Drains the messages queued for other replicas.
+    pub fn take_outbound(&mut self) -> Vec<Envelope<C>> {
+        std::mem::take(&mut self.outbound)
+    }
+
+    /// Drains the commands applied since the last call, in index order.
+    pub fn take_applied(&mut self) -> Vec<Applied<C, S::Output>> {
+        std::mem::take(&mut self.applied)
+    }
+
+    /// Appends `command` to the log and starts replicating it.
+    ///
+    /// Returns the index the command will be applied at if it commits. A
+    /// leader that loses its quorum before then may have the entry replaced by
+    /// its successor; the command is then never applied.
+    pub fn propose(&mut self, command: C, now: Time) -> Result<u64, ReplicationError> {
+        if !self.is_leader(now) {
+            return Err(ReplicationError::NotLeader {
+                leader: self.leader.clone(),
+            });
+        }
+        let index = self.append_local(Some(command));
+        for peer in self.peers.clone() {
+            if self.progress.get(&peer).is_some_and(|p| p.next == index) {
+                self.send_append(&peer, now);
+            }
+        }
+        self.advance_commit();
+        Ok(index)
+    }
+
+    /// Advances timers: heartbeats and lease checks on the leader, elections
+    /// elsewhere.
+    pub fn tick(&mut self, now: Time) {
+        match self.role {
+            Role::Leader => {
+                if !self.is_leader(now) {
+                    crate::tracing_compat::info!(
+                        node = %self.config.node,
+                        term = self.term,
+                        "leadership lease lost; stepping down"
+                    );
+                    self.become_follower(self.term, None, now);
+                    return;
+                }
+                if now >= self.next_heartbeat {
+                    for peer in self.peers.clone() {
+                        self.send_append(&peer, now);
+                    }
+                    self.next_heartbeat = now + self.config.heartbeat_interval;
+                }
+            }
+            Role::Follower | Role::Candidate => {
+                if now >= self.election_deadline {
+                    self.start_election(now);
+                }
+            }
+        }
+    }
+
+    /// Handles a message from another replica.
+    pub fn step(&mut self, envelope: Envelope<C>, now: Time) {
+        let Envelope { from, to, message } = envelope;
+        if to != self.config.node || !self.peers.contains(&from) {
+            return;
+        }
+        match message {
+            ReplicationMessage::RequestVote {
+                term,
+                last_log_index,
+                last_log_term,
+                lease,
+            } => self.on_request_vote(from, term, (last_log_term, last_log_index), &lease, now),
+            ReplicationMessage::Vote {
+                term,
+                granted,
+                lease,
+            } => self.on_vote(from, term, granted, lease.as_ref(), now),
+            ReplicationMessage::Append {
+                term,
+                prev_index,
+                prev_term,
+                entries,
+                leader_commit,
+                lease,
+            } => {
+                if self.accept_leader(&from, term, now) {
+                    let grant = self.grant_lease(&from, &lease, now);
+                    let (success, match_index) =
+                        self.append_entries(prev_index, prev_term, entries, leader_commit);
+                    self.send_ack(&from, success, match_index, Some(grant));
+                }
+            }
+            ReplicationMessage::AppendAck {
+                term,
+                success,
+                match_index,
+                lease,
+            } => self.on_append_ack(&from, term, success, match_index, lease.as_ref(), now),
+            ReplicationMessage::InstallSnapshot {
+                term,
+                last_index,
+                last_term,
+                data,
+                lease,
+            } => {
+                if self.accept_leader(&from, term, now) {
+                    let grant = self.grant_lease(&from, &lease, now);
+                    let (success, match_index) =
+                        self.install_snapshot(last_index, last_term, &data);
+                    self.send_ack(&from, success, match_index, Some(grant));
+                }
+            }
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Elections
+    // -----------------------------------------------------------------------
+
+    fn quorum(&self) -> usize {
+        self.config.members.len() / 2 + 1
+    }
+
+    fn reset_election_deadline(&mut self, now: Time) {
+        let base = self.config.election_timeout;
+        let nanos = u64::try_from(base.as_nanos()).unwrap_or(u64::MAX).max(1);
+        let jitter = Duration::from_nanos(self.rng.next_u64() % nanos);
+        self.election_deadline = now + base + jitter;
+    }
+
+    fn start_election(&mut self, now: Time) {
+        if let Some((_, until)) = &self.granted
+            && now < *until
+        {
+            // Still bound by a lease granted to the current leader.
+            self.election_deadline = *until;
+            return;
+        }
+        self.term += 1;
+        self.role = Role::Candidate;
+        self.voted_for = Some(self.config.node.clone());
+        self.leader = None;
+        self.granted = None;
+        self.progress.clear();
+        self.votes = BTreeSet::from([self.config.node.clone()]);
+        self.held = self
+            .peers
+            .iter()
+            .map(|peer| {
+                let lease = HeldLease::new(
+                    RemoteTaskId::from_raw(self.term),
+                    peer.clone(),
+                    self.config.clock_skew,
+                );
+                (peer.clone(), lease)
+            })
+            .collect();
+        self.reset_election_deadline(now);
+        crate::tracing_compat::debug!(
+            node = %self.config.node,
+            term = self.term,
+            "starting election"
+        );
+        if self.votes.len() >= self.quorum() {
+            self.become_leader(now);
+            return;
+        }
+        let (last_log_index, last_log_term) = (self.last_index(), self.last_term());
+        for peer in self.peers.clone() {
+            let lease = self.lease_request(&peer, now);
+            self.send(
+                &peer,
+                ReplicationMessage::RequestVote {
+                    term: self.term,
+                    last_log_index,
+                    last_log_term,
+                    lease,
+                },
+            );
+        }
+    }
+
+    fn on_request_vote(
+        &mut self,
+        candidate: NodeId,
+        term: u64,
+        candidate_last: (u64, u64),
+        lease: &LeaseRenewal,
+        now: Time,
+    ) {
+        let bound_elsewhere = self
+            .granted
+            .as_ref()
+            .is_some_and(|(holder, until)| *holder != candidate && now < *until);
+        if !bound_elsewhere && term > self.term {
+            self.become_follower(term, None, now);
+        }
+        let granted = !bound_elsewhere
+            && term == self.term
+            && self
+                .voted_for
+                .as_ref()
+                .is_none_or(|voted| *voted == candidate)
+            && candidate_last >= (self.last_term(), self.last_index());
+        let grant = if granted {
+            self.voted_for = Some(candidate.clone());
+            self.reset_election_deadline(now);
+            Some(self.grant_lease(&candidate, lease, now))
+        } else {
+            None
+        };
+        self.send(
+            &candidate,
+            ReplicationMessage::Vote {
+                term: self.term,
+                granted,
+                lease: grant,
+            },
+        );
+    }
+
+    fn on_vote(
+        &mut self,
+        voter: NodeId,
+        term: u64,
+        granted: bool,
+        lease: Option<&LeaseRenewal>,
+        now: Time,
+    ) {
+        if term > self.term {
+            self.become_follower(term, None, now);
+            return;
+        }
+        if self.role != Role::Candidate || term != self.term || !granted {
+            return;
+        }
+        if let (Some(lease), Some(held)) = (lease, self.held.get_mut(&voter)) {
+            let _ = held.on_grant(lease, now);
+        }
+        self.votes.insert(voter);
+        if self.votes.len() >= self.quorum() {
+            self.become_leader(now);
+        }
+    }
+
+    fn become_follower(&mut self, term: u64, leader: Option<NodeId>, now: Time) {
+        if term > self.term {
+            self.term = term;
+            self.voted_for = None;
+        }
+        self.role = Role::Follower;
+        self.leader = leader;
+        self.votes.clear();
+        self.progress.clear();
+        self.held.clear();
+        self.reset_election_deadline(now);
+    }
+
+    fn become_leader(&mut self, now: Time) {
+        crate::tracing_compat::info!(
+            node = %self.config.node,
+            term = self.term,
+            "elected leader"
+        );
+        self.role = Role::Leader;
+        self.leader = Some(self.config.node.clone());
+        let next = self.last_index() + 1;
+        self.progress = self
+            .peers
+            .iter()
+            .map(|peer| (peer.clone(), Progress { next, matched: 0 }))
+            .collect();
+        // Committing a barrier from this term commits everything before it.
+        self.append_local(None);
+        for peer in self.peers.clone() {
+            self.send_append(&peer, now);
+        }
+        self.next_heartbeat = now + self.config.heartbeat_interval;
+        self.advance_commit();
+    }
+
+    /// Handles the term check for a leader-originated message, replying with
+    /// a rejection when the sender's term is stale.
+    fn accept_leader(&mut self, leader: &NodeId, term: u64, now: Time) -> bool {
+        if term < self.term {
+            self.send_ack(leader, false, 0, None);
+            return false;
+        }
+        debug_assert!(
+            term > self.term || self.role != Role::Leader,
+            "two leaders in term {term}"
+        );
+        self.become_follower(term, Some(leader.clone()), now);
+        true
+    }
+
+    // -----------------------------------------------------------------------
+    // Leases
+    // -----------------------------------------------------------------------
+
+    fn lease_request(&self, peer: &NodeId, now: Time) -> LeaseRenewal {
+        self.held.get(peer).map_or_else(
+            || {
+                HeldLease::new(
+                    RemoteTaskId::from_raw(self.term),
+                    peer.clone(),
+                    self.config.clock_skew,
+                )
+                .renewal_request(self.config.node.clone(), self.config.lease_duration, now)
+            },
+            |held| held.renewal_request(self.config.node.clone(), self.config.lease_duration, now),
+        )
+    }
+
+    /// Grants (or renews) the leadership lease to `holder` and records the
+    /// promise not to vote for anyone else until it runs out.
+    fn grant_lease(&mut self, holder: &NodeId, request: &LeaseRenewal, now: Time) -> LeaseRenewal {
+        let lease = request.new_lease.min(self.config.lease_duration);
+        let skew = self.config.clock_skew;
+        let clock = self
+            .peer_clocks
+            .entry(holder.clone())
+            .or_insert_with(|| PeerClock::new(holder.clone(), skew));
+        // Excess skew is already traced by `observe`; the holder shortens its
+        // own view of the lease accordingly.
+        let _ = clock.observe(&request.timing, now);
+        let timing = clock.stamp(now);
+        self.granted = Some((holder.clone(), now + lease));
+        LeaseRenewal {
+            remote_task_id: request.remote_task_id,
+            new_lease: lease,
+            current_state: RemoteTaskState::Running,
+            node: self.config.node.clone(),
+            timing,
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Log
+    // -----------------------------------------------------------------------
+
+    fn last_term(&self) -> u64 {
+        self.log.last().map_or(self.snapshot.term, |entry| entry.term)
+    }
+
+    /// Returns the term at `index`, `None` if it is beyond the log or
+    /// compacted into the snapshot.
+    fn term_at(&self, index: u64) -> Option<u64> {
+        if index == self.snapshot.index {
+            return Some(self.snapshot.term);
+        }
+        if index < self.snapshot.index {
+            return None;
+        }
+        self.log.get(self.offset(index)).map(|entry| entry.term)
+    }
+
+    fn offset(&self, index: u64) -> usize {
+        (index - self.snapshot.index - 1) as usize
+    }
+
+    fn append_local(&mut self, command: Option<C>) -> u64 {
+        let index = self.last_index() + 1;
+        self.log.push(LogEntry {
+            term: self.term,
+            index,
+            command,
+        });
+        index
+    }
+
+    fn append_entries(
+        &mut self,
+        prev_index: u64,
+        prev_term: u64,
+        entries: Vec<LogEntry<C>>,
+        leader_commit: u64,
+    ) -> (bool, u64) {
+        if prev_index > self.last_index() {
+            return (false, self.last_index());
+        }
+        // Anything at or below the snapshot is committed and therefore
+        // already matches; only later positions need the term check.
+        if prev_index >= self.snapshot.index && self.term_at(prev_index) != Some(prev_term) {
+            return (false, self.commit_index.min(prev_index.saturating_sub(1)));
+        }
+        let match_index = prev_index + entries.len() as u64;
+        for entry in entries {
+            if entry.index <= self.snapshot.index {
+                continue;
+            }
+            match self.term_at(entry.index) {
+                Some(term) if term == entry.term => {}
+                Some(_) => {
+                    assert!(
+                        entry.index > self.commit_index,
+                        "leader overwrote committed index {}",
+                        entry.index
+                    );
+                    let offset = self.offset(entry.index);
+                    self.log.truncate(offset);
+                    self.log.push(entry);
+                }
+                None => self.log.push(entry),
+            }
+        }
+        let target = leader_commit.min(match_index);
+        if target > self.commit_index {
+            self.commit_index = target;
+            self.apply_committed();
+        }
+        (true, match_index)
+    }
+
+    fn install_snapshot(&mut self, last_index: u64, last_term: u64, data: &[u8]) -> (bool, u64) {
+        if last_index <= self.commit_index {
+            return (true, self.commit_index);
+        }
+        if let Err(_error) = self.machine.restore(data) {
+            crate::tracing_compat::warn!(
+                node = %self.config.node,
+                last_index,
+                error = %_error,
+                "rejecting snapshot that failed to restore"
+            );
+            return (false, self.commit_index);
+        }
+        if self.term_at(last_index) == Some(last_term) {
+            let offset = self.offset(last_index);
+            self.log.drain(..=offset);
+        } else {
+            self.log.clear();
+        }
+        self.snapshot = Snapshot {
+            index: last_index,
+            term: last_term,
+            data: data.to_vec(),
+        };
+        self.commit_index = last_index;
+        self.last_applied = last_index;
+        (true, last_index)
+    }
+
+    fn on_append_ack(
+        &mut self,
+        follower: &NodeId,
+        term: u64,
+        success: bool,
+        match_index: u64,
+        lease: Option<&LeaseRenewal>,
+        now: Time,
+    ) {
+        if term > self.term {
+            self.become_follower(term, None, now);
+            return;
+        }
+        if self.role != Role::Leader || term != self.term {
+            return;
+        }
+        if let (Some(lease), Some(held)) = (lease, self.held.get_mut(follower)) {
+            let _ = held.on_grant(lease, now);
+        }
+        let last_index = self.last_index();
+        let Some(progress) = self.progress.get_mut(follower) else {
+            return;
+        };
+        if success {
+            progress.matched = progress.matched.max(match_index);
+            progress.next = progress.next.max(progress.matched + 1);
+            let behind = progress.next <= last_index;
+            self.advance_commit();
+            if behind {
+                self.send_append(follower, now);
+            }
+        } else {
+            let next = (match_index + 1).min(progress.next.saturating_sub(1));
+            progress.next = next.max(progress.matched + 1);
+            self.send_append(follower, now);
+        }
+    }
+
+    fn send_append(&mut self, peer: &NodeId, now: Time) {
+        let lease = self.lease_request(peer, now);
+        let last_index = self.last_index();
+        let Some(progress) = self.progress.get_mut(peer) else {
+            return;
+        };
+        let next = progress.next.min(last_index + 1);
+        let message = if next <= self.snapshot.index {
+            progress.next = self.snapshot.index + 1;
+            ReplicationMessage::InstallSnapshot {
+                term: self.term,
+                last_index: self.snapshot.index,
+                last_term: self.snapshot.term,
+                data: self.snapshot.data.clone(),
+                lease,
+            }
+        } else {
+            let start = (next - self.snapshot.index - 1) as usize;
+            let entries: Vec<_> = self.log[start..]
+                .iter()
+                .take(self.config.max_batch)
+                .cloned()
+                .collect();
+            progress.next = next + entries.len() as u64;
+            let prev_index = next - 1;
+            let prev_term = self
+                .term_at(prev_index)
+                .expect("entry preceding next index is in the log");
+            ReplicationMessage::Append {
+                term: self.term,
+                prev_index,
+                prev_term,
+                entries,
+                leader_commit: self.commit_index,
+                lease,
+            }
+        };
+        self.send(peer, message);
+    }
+
+    fn send_ack(
+        &mut self,
+        to: &NodeId,
+        success: bool,
+        match_index: u64,
+        lease: Option<LeaseRenewal>,
+    ) {
+        self.send(
+            to,
+            ReplicationMessage::AppendAck {
+                term: self.term,
+                success,
+                match_index,
+                lease,
+            },
+        );
+    }
+
+    fn send(&mut self, to: &NodeId, message: ReplicationMessage<C>) {
+        self.outbound.push(Envelope {
+            from: self.config.node.clone(),
+            to: to.clone(),
+            message,
+        });
+    }
+
+    /// Commits the highest current-term index acknowledged by a quorum.
+    ///
+    /// Entries from earlier terms are never counted directly; they commit
+    /// when a current-term entry after them does.
+    fn advance_commit(&mut self) {
+        let quorum = self.quorum();
+        let mut index = self.last_index();
+        while index > self.commit_index && self.term_at(index) == Some(self.term) {
+            let acks = self
+                .progress
+                .values()
+                .filter(|progress| progress.matched >= index)
+                .count();
+            if acks + 1 >= quorum {
+                self.commit_index = index;
+                self.apply_committed();
+                return;
+            }
+            index -= 1;
+        }
+    }
+
+    fn apply_committed(&mut self) {
+        while self.last_applied < self.commit_index {
+            let index = self.last_applied + 1;
+            let entry = &self.log[self.offset(index)];
+            let term = entry.term;
+            if let Some(command) = entry.command.clone() {
+                let output = self.machine.apply(index, &command);
+                self.applied.push(Applied {
+                    index,
+                    term,
+                    command,
+                    output,
+                });
+            }
+            self.last_applied = index;
+        }
+        self.maybe_snapshot();
+    }
+
+    fn maybe_snapshot(&mut self) {
+        let threshold = self.config.snapshot_threshold;
+        if threshold == 0 || self.last_applied - self.snapshot.index < threshold {
+            return;
+        }
+        let index = self.last_applied;
+        let term = self.term_at(index).expect("applied entry is in the log");
+        let offset = self.offset(index);
+        self.log.drain(..=offset);
+        self.snapshot = Snapshot {
+            index,
+            term,
+            data: self.machine.snapshot(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum KvOp {
+        Put { id: u64, key: u8, value: u64 },
+        Get { id: u64, key: u8 },
+    }
+
+    impl KvOp {
+        fn id(&self) -> u64 {
+            match self {
+                Self::Put { id, .. } | Self::Get { id, .. } => *id,
+            }
+        }
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    struct Kv {
+        data: BTreeMap<u8, u64>,
+    }
+
+    impl StateMachine<KvOp> for Kv {
+        type Output = Option<u64>;
+
+        fn apply(&mut self, _index: u64, op: &KvOp) -> Option<u64> {
+            match op {
+                KvOp::Put { key, value, .. } => self.data.insert(*key, *value),
+                KvOp::Get { key, .. } => self.data.get(key).copied(),
+            }
+        }
+
+        fn snapshot(&self) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            for (key, value) in &self.data {
+                bytes.push(*key);
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes
+        }
+
+        fn restore(&mut self, snapshot: &[u8]) -> Result<(), String> {
+            if snapshot.len() % 9 != 0 {
+                return Err(format!("truncated snapshot of {} bytes", snapshot.len()));
+            }
+            self.data = snapshot
+                .chunks_exact(9)
+                .map(|chunk| {
+                    let value = u64::from_le_bytes(chunk[1..].try_into().expect("8 bytes"));
+                    (chunk[0], value)
+                })
+                .collect();
+            Ok(())
+        }
+    }
+
+    type Node = ReplicatedLog<KvOp, Kv>;
+
+    const TICK: Duration = Duration::from_millis(1);
+
+    /// Deterministic in-memory network: per-message latency from a seeded
+    /// RNG, partitions as group assignments, crashed nodes frozen in place.
+    struct Sim {
+        names: Vec<NodeId>,
+        nodes: BTreeMap<NodeId, Node>,
+        now: Time,
+        rng: DetRng,
+        in_flight: Vec<(Time, u64, Envelope<KvOp>)>,
+        sent: u64,
+        groups: BTreeMap<NodeId, usize>,
+        down: BTreeSet<NodeId>,
+        applied: BTreeMap<NodeId, Vec<Applied<KvOp, Option<u64>>>>,
+        /// Every command applied anywhere, by index. Divergence panics.
+        committed: BTreeMap<u64, KvOp>,
+    }
+
+    impl Sim {
+        fn new(seed: u64, size: usize, snapshot_threshold: u64) -> Self {
+            let names: Vec<NodeId> = (0..size).map(|i| NodeId::new(format!("n{i}"))).collect();
+            let now = Time::ZERO;
+            let nodes = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let config = ReplicationConfig::new(name.clone(), names.clone())
+                        .with_snapshot_threshold(snapshot_threshold)
+                        .with_max_batch(8)
+                        .with_seed(seed.wrapping_mul(31).wrapping_add(i as u64 + 1));
+                    let node = Node::new(config, Kv::default(), now).expect("valid config");
+                    (name.clone(), node)
+                })
+                .collect();
+            Self {
+                groups: names.iter().map(|name| (name.clone(), 0)).collect(),
+                applied: names.iter().map(|name| (name.clone(), Vec::new())).collect(),
+                names,
+                nodes,
+                now,
+                rng: DetRng::new(seed ^ 0x5eed),
+                in_flight: Vec::new(),
+                sent: 0,
+                down: BTreeSet::new(),
+                committed: BTreeMap::new(),
+            }
+        }
+
+        fn name(&self, i: usize) -> NodeId {
+            self.names[i].clone()
+        }
+
+        fn node(&self, name: &NodeId) -> &Node {
+            &self.nodes[name]
+        }
+
+        fn connected(&self, a: &NodeId, b: &NodeId) -> bool {
+            self.groups[a] == self.groups[b]
+        }
+
+        fn partition(&mut self, groups: &[&[usize]]) {
+            for (group, members) in groups.iter().enumerate() {
+                for &member in *members {
+                    self.groups.insert(self.names[member].clone(), group);
+                }
+            }
+        }
+
+        fn heal(&mut self) {
+            for group in self.groups.values_mut() {
+                *group = 0;
+            }
+        }
+
+        /// Advances one tick and returns the commands applied during it.
+        fn step(&mut self) -> Vec<(NodeId, Applied<KvOp, Option<u64>>)> {
+            self.now = self.now + TICK;
+            let now = self.now;
+            let (mut due, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.in_flight)
+                .into_iter()
+                .partition(|(at, _, _)| *at <= now);
+            self.in_flight = rest;
+            due.sort_by_key(|(at, seq, _)| (*at, *seq));
+            for (_, _, envelope) in due {
+                let blocked = self.down.contains(&envelope.to)
+                    || !self.connected(&envelope.from, &envelope.to);
+                if blocked {
+                    continue;
+                }
+                let node = self.nodes.get_mut(&envelope.to).expect("known node");
+                node.step(envelope, now);
+            }
+            let mut applied = Vec::new();
+            for name in self.names.clone() {
+                if self.down.contains(&name) {
+                    continue;
+                }
+                let node = self.nodes.get_mut(&name).expect("known node");
+                node.tick(now);
+                let outbound = node.take_outbound();
+                let new = node.take_applied();
+                for envelope in outbound {
+                    let latency = Duration::from_millis(1 + self.rng.next_u64() % 4);
+                    self.sent += 1;
+                    self.in_flight.push((now + latency, self.sent, envelope));
+                }
+                for record in new {
+                    self.check_applied(&name, &record);
+                    applied.push((name.clone(), record));
+                }
+            }
+            applied
+        }
+
+        fn check_applied(&mut self, name: &NodeId, record: &Applied<KvOp, Option<u64>>) {
+            let history = self.applied.get_mut(name).expect("known node");
+            if let Some(last) = history.last() {
+                assert!(last.index < record.index, "{name} applied out of order");
+            }
+            history.push(record.clone());
+            let existing = self
+                .committed
+                .entry(record.index)
+                .or_insert_with(|| record.command.clone());
+            assert_eq!(
+                *existing, record.command,
+                "divergent commands at index {}",
+                record.index
+            );
+        }
+
+        fn run_for(&mut self, duration: Duration) -> Vec<(NodeId, Applied<KvOp, Option<u64>>)> {
+            let ticks = duration.as_millis();
+            let mut applied = Vec::new();
+            for _ in 0..ticks {
+                applied.extend(self.step());
+            }
+            applied
+        }
+
+        fn leader(&self) -> Option<NodeId> {
+            self.nodes
+                .iter()
+                .filter(|(name, node)| !self.down.contains(*name) && node.is_leader(self.now))
+                .max_by_key(|(_, node)| node.term())
+                .map(|(name, _)| name.clone())
+        }
+
+        fn run_until_leader(&mut self) -> NodeId {
+            for _ in 0..10_000 {
+                if let Some(leader) = self.leader() {
+                    return leader;
+                }
+                self.step();
+            }
+            panic!("no leader elected within 10s");
+        }
+
+        fn propose(&mut self, name: &NodeId, op: KvOp) -> Result<u64, ReplicationError> {
+            let now = self.now;
+            self.nodes
+                .get_mut(name)
+                .expect("known node")
+                .propose(op, now)
+        }
+
+        fn assert_converged(&self) {
+            let reference = self.node(&self.names[0]);
+            for name in &self.names {
+                let node = self.node(name);
+                assert_eq!(node.last_applied(), reference.last_applied(), "{name} lags");
+                assert_eq!(node.state_machine(), reference.state_machine(), "{name} differs");
+            }
+        }
+    }
+
+    fn put(id: u64, value: u64) -> KvOp {
+        KvOp::Put {
+            id,
+            key: (id % 3) as u8,
+            value,
+        }
+    }
+
+    #[test]
+    fn config_validation_rejects_inconsistent_timing() {
+        init_test("config_validation_rejects_inconsistent_timing");
+        let a = NodeId::new("a");
+        let members = [a.clone(), NodeId::new("b")];
+        let ok = ReplicationConfig::new(a.clone(), members.clone()).validate();
+        crate::assert_with_log!(ok.is_ok(), "defaults valid", true, ok.is_ok());
+
+        let long_lease = ReplicationConfig::new(a.clone(), members.clone())
+            .with_lease_duration(Duration::from_secs(1))
+            .validate();
+        let rejected = matches!(long_lease, Err(ReplicationError::InvalidConfig(_)));
+        crate::assert_with_log!(rejected, "lease longer than timeout", true, rejected);
+
+        let outsider = ReplicationConfig::new(NodeId::new("c"), members).validate();
+        let rejected = matches!(outsider, Err(ReplicationError::InvalidConfig(_)));
+        crate::assert_with_log!(rejected, "node outside members", true, rejected);
+        crate::test_complete!("config_validation_rejects_inconsistent_timing");
+    }
+
+    #[test]
+    fn failover_preserves_committed_commands_across_seeds() {
+        init_test("failover_preserves_committed_commands_across_seeds");
+        for seed in 0..12 {
+            let mut sim = Sim::new(seed, 3, 0);
+            let first = sim.run_until_leader();
+            for id in 0..5 {
+                sim.propose(&first, put(id, id * 10)).expect("leader accepts");
+            }
+            sim.run_for(Duration::from_millis(100));
+            let committed = sim.committed.len();
+            crate::assert_with_log!(committed == 5, "first batch committed", 5, committed);
+
+            // Crash the leader right after it accepts one more command.
+            let _ = sim.propose(&first, put(99, 990));
+            sim.down.insert(first.clone());
+            let second = sim.run_until_leader();
+            crate::assert_with_log!(second != first, "new leader", true, second != first);
+            for id in 5..10 {
+                sim.propose(&second, put(id, id * 10)).expect("new leader accepts");
+            }
+            sim.run_for(Duration::from_millis(200));
+
+            sim.down.clear();
+            sim.run_for(Duration::from_secs(1));
+            let leader = sim.run_until_leader();
+            sim.propose(&leader, put(100, 1000)).expect("leader accepts");
+            sim.run_for(Duration::from_millis(200));
+            sim.assert_converged();
+
+            let ids: Vec<u64> = sim.committed.values().map(KvOp::id).collect();
+            let distinct: BTreeSet<u64> = ids.iter().copied().collect();
+            crate::assert_with_log!(
+                distinct.len() == ids.len(),
+                "no command applied twice",
+                ids.len(),
+                distinct.len()
+            );
+            let all_present = (0..10).chain([100]).all(|id| distinct.contains(&id));
+            crate::assert_with_log!(all_present, "committed commands kept", true, all_present);
+        }
+        crate::test_complete!("failover_preserves_committed_commands_across_seeds");
+    }
+
+    #[test]
+    fn quorum_loss_blocks_progress() {
+        init_test("quorum_loss_blocks_progress");
+        let mut sim = Sim::new(7, 3, 0);
+        let leader = sim.run_until_leader();
+        sim.propose(&leader, put(0, 1)).expect("leader accepts");
+        sim.run_for(Duration::from_millis(100));
+        let base = sim.committed.len();
+        crate::assert_with_log!(base == 1, "baseline committed", 1, base);
+
+        // Leader alone in a minority: it may accept, but never commits.
+        let li = sim.names.iter().position(|n| *n == leader).expect("leader");
+        let others: Vec<usize> = (0..3).filter(|i| *i != li).collect();
+        sim.partition(&[&[li], others.as_slice()]);
+        let stranded = sim.propose(&leader, put(1, 2)).expect("lease still held");
+        sim.run_for(Duration::from_millis(600));
+        let deposed = !sim.node(&leader).is_leader(sim.now);
+        crate::assert_with_log!(deposed, "minority leader steps down", true, deposed);
+        let commit = sim.node(&leader).commit_index();
+        crate::assert_with_log!(commit < stranded, "stranded entry uncommitted", stranded, commit);
+
+        let majority = sim.run_until_leader();
+        sim.propose(&majority, put(2, 3)).expect("majority leader accepts");
+        sim.run_for(Duration::from_millis(100));
+        let has_two = sim.committed.values().any(|op| op.id() == 2);
+        crate::assert_with_log!(has_two, "majority makes progress", true, has_two);
+
+        // No majority anywhere: nothing commits and nobody leads.
+        sim.partition(&[&[0], &[1], &[2]]);
+        let _ = sim.propose(&majority, put(3, 4));
+        let before = sim.committed.clone();
+        sim.run_for(Duration::from_secs(2));
+        crate::assert_with_log!(
+            sim.committed == before,
+            "no commits without quorum",
+            before.len(),
+            sim.committed.len()
+        );
+        let leaderless = sim.leader().is_none();
+        crate::assert_with_log!(leaderless, "no leader without quorum", true, leaderless);
+        for name in sim.names.clone() {
+            let refused = matches!(
+                sim.propose(&name, put(4, 5)),
+                Err(ReplicationError::NotLeader { .. })
+            );
+            crate::assert_with_log!(refused, "proposal refused", true, refused);
+        }
+
+        sim.heal();
+        let leader = sim.run_until_leader();
+        sim.propose(&leader, put(5, 6)).expect("leader accepts");
+        sim.run_for(Duration::from_millis(300));
+        let has_five = sim.committed.values().any(|op| op.id() == 5);
+        crate::assert_with_log!(has_five, "progress resumes after heal", true, has_five);
+        let stranded_lost = !sim.committed.values().any(|op| op.id() == 1);
+        crate::assert_with_log!(stranded_lost, "stranded entry replaced", true, stranded_lost);
+        sim.assert_converged();
+        crate::test_complete!("quorum_loss_blocks_progress");
+    }
+
+    #[test]
+    fn snapshot_truncates_log_and_restores_lagging_follower() {
+        init_test("snapshot_truncates_log_and_restores_lagging_follower");
+        let mut sim = Sim::new(3, 3, 4);
+        let leader = sim.run_until_leader();
+        let li = sim.names.iter().position(|n| *n == leader).expect("leader");
+        let lagging = (li + 1) % 3;
+        let lagging_name = sim.name(lagging);
+        let rest: Vec<usize> = (0..3).filter(|i| *i != lagging).collect();
+        sim.partition(&[rest.as_slice(), &[lagging]]);
+
+        for id in 0..20 {
+            sim.propose(&leader, put(id, id + 1)).expect("leader accepts");
+            sim.run_for(Duration::from_millis(5));
+        }
+        sim.run_for(Duration::from_millis(100));
+        let node = sim.node(&leader);
+        let snapshot_index = node.snapshot_index();
+        crate::assert_with_log!(snapshot_index >= 16, "leader snapshotted", 16, snapshot_index);
+        let held = node.entries().len() as u64;
+        crate::assert_with_log!(held < 4, "log truncated", "< 4", held);
+        let lag = sim.node(&lagging_name).last_applied();
+        crate::assert_with_log!(lag <= 1, "follower isolated", "<= 1", lag);
+
+        sim.heal();
+        sim.run_for(Duration::from_secs(1));
+        let leader = sim.run_until_leader();
+        sim.propose(&leader, put(20, 21)).expect("leader accepts");
+        sim.run_for(Duration::from_millis(200));
+        sim.assert_converged();
+        let restored = sim.node(&lagging_name).snapshot_index();
+        crate::assert_with_log!(restored >= 16, "follower installed snapshot", 16, restored);
+        let replayed = sim.applied[&lagging_name].len();
+        crate::assert_with_log!(replayed < 21, "snapshot skipped replay", "< 21", replayed);
+        let value = sim.node(&lagging_name).state_machine().data.get(&2).copied();
+        crate::assert_with_log!(value == Some(21), "restored state", Some(21), value);
+        crate::test_complete!("snapshot_truncates_log_and_restores_lagging_follower");
+    }
+
+    struct Invocation {
+        op: KvOp,
+        node: NodeId,
+        invoked: Time,
+        completed: Option<(Time, Option<u64>)>,
+    }
+
+    /// Checks a client history against the committed log: every completed
+    /// operation sits in the log, observed exactly what a sequential replay
+    /// produces at its index, and operations that finished before another
+    /// began precede it in the log.
+    fn check_linearizable(committed: &BTreeMap<u64, KvOp>, history: &[Invocation]) {
+        let mut replay = Kv::default();
+        let mut position = BTreeMap::new();
+        let mut outputs = BTreeMap::new();
+        for (index, op) in committed {
+            assert!(position.insert(op.id(), *index).is_none(), "op applied twice");
+            outputs.insert(op.id(), replay.apply(*index, op));
+        }
+        let completed: Vec<_> = history
+            .iter()
+            .filter_map(|inv| inv.completed.map(|(at, out)| (inv, at, out)))
+            .collect();
+        for (inv, _, output) in &completed {
+            let id = inv.op.id();
+            assert!(position.contains_key(&id), "completed op {id} not in log");
+            assert_eq!(outputs[&id], *output, "op {id} saw a non-sequential result");
+        }
+        for (a, a_done, _) in &completed {
+            for b in history {
+                let (a_id, b_id) = (a.op.id(), b.op.id());
+                if *a_done < b.invoked
+                    && let Some(b_index) = position.get(&b_id)
+                {
+                    assert!(
+                        position[&a_id] < *b_index,
+                        "op {a_id} finished before {b_id} began but is ordered after it"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_kv_histories_are_linearizable() {
+        init_test("random_kv_histories_are_linearizable");
+        for seed in 0..8 {
+            let mut sim = Sim::new(seed, 5, 6);
+            let mut rng = DetRng::new(seed + 100);
+            let mut history: Vec<Invocation> = Vec::new();
+            let mut next_id = 0;
+            for round in 0..4_000 {
+                if round % 250 == 249 {
+                    sim.down.clear();
+                    match rng.next_u64() % 4 {
+                        0 => sim.heal(),
+                        1 => {
+                            let split = 1 + (rng.next_u64() % 4) as usize;
+                            let (left, right): (Vec<usize>, Vec<usize>) =
+                                (0..5).partition(|i| *i < split);
+                            sim.partition(&[left.as_slice(), right.as_slice()]);
+                        }
+                        2 => {
+                            let victim = sim.name((rng.next_u64() % 5) as usize);
+                            sim.down.insert(victim);
+                        }
+                        _ => {
+                            if let Some(leader) = sim.leader() {
+                                sim.down.insert(leader);
+                            }
+                        }
+                    }
+                }
+                if round < 3_500
+                    && rng.next_u64() % 10 == 0
+                    && let Some(leader) = sim.leader()
+                {
+                    let key = (rng.next_u64() % 3) as u8;
+                    let op = if rng.next_bool() {
+                        KvOp::Put {
+                            id: next_id,
+                            key,
+                            value: next_id,
+                        }
+                    } else {
+                        KvOp::Get { id: next_id, key }
+                    };
+                    next_id += 1;
+                    if sim.propose(&leader, op.clone()).is_ok() {
+                        history.push(Invocation {
+                            op,
+                            node: leader,
+                            invoked: sim.now,
+                            completed: None,
+                        });
+                    }
+                }
+                for (node, record) in sim.step() {
+                    let id = record.command.id();
+                    if let Some(inv) = history
+                        .iter_mut()
+                        .find(|inv| inv.op.id() == id && inv.node == node)
+                    {
+                        inv.completed = Some((sim.now, record.output));
+                    }
+                }
+            }
+            sim.down.clear();
+            sim.heal();
+            sim.run_for(Duration::from_secs(2));
+            let leader = sim.run_until_leader();
+            sim.propose(&leader, KvOp::Get { id: next_id, key: 0 })
+                .expect("leader accepts");
+            sim.run_for(Duration::from_millis(300));
+            sim.assert_converged();
+
+            let done = history.iter().filter(|inv| inv.completed.is_some()).count();
+            crate::assert_with_log!(done > 20, "history has completions", "> 20", done);
+            check_linearizable(&sim.committed, &history);
+        }
+        crate::test_complete!("random_kv_histories_are_linearizable");
+    }
+}
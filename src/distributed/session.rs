@@ -0,0 +1,1689 @@
+//! Session establishment between cluster nodes, with resumption tickets.
+//!
+//! A full handshake takes three round trips before the connection can carry
+//! traffic:
+//!
+//! ```text
+//! Hello        -> HelloAck       capability sets, intersected
+//! SchemaOffer  -> SchemaAccept   highest common version of every shared schema
+//! KeyShare     -> KeyShareAck    nonces; session key derived from the cluster key
+//! ```
+//!
+//! When the responder has a [`TicketAuthority`], `KeyShareAck` also carries a
+//! [`ResumptionTicket`]: an opaque, authenticated, time-limited blob that
+//! records the negotiated parameters (both capability-set hashes, the schema
+//! versions, and the key epoch). On reconnect the initiator sends `Resume`
+//! with the ticket and gets `ResumeAccept` back, so traffic starts after one
+//! round trip. If the responder refuses, it answers `ResumeReject` with a
+//! [`ResumeRejection`] and the initiator continues with `Hello` on the same
+//! connection.
+//!
+//! # Tickets
+//!
+//! The ticket body is authenticated with HMAC-SHA256 under the authority's
+//! [`KeyRing`]; it carries no secret. Each ticket has a resumption secret,
+//! derived from the ticket key and the body, which the responder hands to the
+//! initiator alongside the ticket. The initiator proves possession of it with
+//! a binder over its nonce and capability hash, and both sides derive the
+//! resumed session key from it. A ticket is therefore useless to anyone who
+//! only observed it on the wire.
+//!
+//! The responder rejects a ticket whose key epoch has left the ring, whose
+//! tag or binder does not verify, which has expired, which names other
+//! peers, whose capability hashes no longer match either side, or which has
+//! been redeemed [`TicketPolicy::max_uses`] times. Redemption counts are kept
+//! in memory, so a responder restart forgets them; keep ticket lifetimes short
+//! or rotate the ticket key on restart.
+//!
+//! Like [`super::membership::Swim`], both sides are pure state machines: the
+//! caller moves [`HandshakeMessage`]s across its transport and supplies the
+//! current time. Outcomes are reported as [`SessionEvent`]s.
+
+use crate::remote::NodeId;
+use crate::security::{AuthKey, KeyRing};
+use crate::tracing_compat::{info, warn};
+use crate::types::Time;
+use crate::util::DetRng;
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of handshake nonces, capability hashes, binders, and ticket tags.
+const DIGEST_LEN: usize = 32;
+
+/// Ticket body format version.
+const TICKET_VERSION: u8 = 1;
+
+const SESSION_KEY_CONTEXT: &[u8] = b"asupersync-session-key-v1";
+const RESUMED_KEY_CONTEXT: &[u8] = b"asupersync-session-resumed-key-v1";
+const RESUMPTION_SECRET_LABEL: &[u8] = b"asupersync-resumption-secret-v1";
+
+/// Inclusive range of versions a node supports for one schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaRange {
+    /// Oldest supported version.
+    pub min: u32,
+    /// Newest supported version.
+    pub max: u32,
+}
+
+impl SchemaRange {
+    /// Creates a range.
+    #[must_use]
+    pub const fn new(min: u32, max: u32) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the highest version both ranges contain.
+    #[must_use]
+    pub fn highest_common(self, other: Self) -> Option<u32> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        (min <= max).then_some(max)
+    }
+}
+
+/// What one node brings to a handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionOffer {
+    /// The local node.
+    pub node: NodeId,
+    /// Capabilities the node supports.
+    pub capabilities: BTreeSet<String>,
+    /// Supported versions of each schema the node speaks.
+    pub schemas: BTreeMap<String, SchemaRange>,
+}
+
+impl SessionOffer {
+    /// Creates an offer with no capabilities or schemas.
+    #[must_use]
+    pub fn new(node: NodeId) -> Self {
+        Self {
+            node,
+            capabilities: BTreeSet::new(),
+            schemas: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a capability.
+    #[must_use]
+    pub fn with_capability(mut self, capability: impl Into<String>) -> Self {
+        self.capabilities.insert(capability.into());
+        self
+    }
+
+    /// Adds a schema and its supported versions.
+    #[must_use]
+    pub fn with_schema(mut self, schema: impl Into<String>, range: SchemaRange) -> Self {
+        self.schemas.insert(schema.into(), range);
+        self
+    }
+
+    /// Returns the SHA-256 hash of the capability set.
+    #[must_use]
+    pub fn capability_hash(&self) -> [u8; DIGEST_LEN] {
+        capability_hash(&self.capabilities)
+    }
+}
+
+fn capability_hash(capabilities: &BTreeSet<String>) -> [u8; DIGEST_LEN] {
+    let mut hasher = Sha256::new();
+    for capability in capabilities {
+        hasher.update((capability.len() as u64).to_be_bytes());
+        hasher.update(capability.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Opaque resumption ticket, as issued by a [`TicketAuthority`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResumptionTicket(Vec<u8>);
+
+impl ResumptionTicket {
+    /// Wraps ticket bytes received from a peer.
+    #[must_use]
+    pub const fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the encoded ticket.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ResumptionTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ResumptionTicket({} bytes)", self.0.len())
+    }
+}
+
+/// A freshly issued ticket and its resumption secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedTicket {
+    /// The ticket to present on reconnect.
+    pub ticket: ResumptionTicket,
+    /// Secret that binds the ticket to its holder.
+    pub secret: AuthKey,
+    /// When the responder stops accepting the ticket.
+    pub expires_at: Time,
+}
+
+/// Why a responder refused a resumption attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResumeRejection {
+    /// The responder does not issue tickets.
+    Disabled,
+    /// The ticket could not be decoded.
+    Malformed,
+    /// The ticket key has been retired from the ring.
+    UnknownKey,
+    /// The ticket's authentication tag does not verify.
+    BadAuthenticator,
+    /// The ticket is past its expiry.
+    Expired,
+    /// The ticket has already been redeemed as often as the policy allows.
+    Replayed,
+    /// The ticket was issued to a different pair of nodes.
+    WrongPeer,
+    /// The capability set of either node changed since the ticket was issued.
+    CapabilitiesChanged,
+    /// The initiator did not prove possession of the resumption secret.
+    BadBinder,
+}
+
+impl ResumeRejection {
+    /// Returns a stable name for logs and metrics.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Malformed => "malformed",
+            Self::UnknownKey => "unknown_key",
+            Self::BadAuthenticator => "bad_authenticator",
+            Self::Expired => "expired",
+            Self::Replayed => "replayed",
+            Self::WrongPeer => "wrong_peer",
+            Self::CapabilitiesChanged => "capabilities_changed",
+            Self::BadBinder => "bad_binder",
+        }
+    }
+}
+
+impl fmt::Display for ResumeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Why an initiator ran the full handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackReason {
+    /// No ticket was held for the peer.
+    NoTicket,
+    /// The responder rejected the ticket.
+    Rejected(ResumeRejection),
+}
+
+/// Message exchanged during session establishment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandshakeMessage {
+    /// Initiator identity and capabilities.
+    Hello {
+        /// Initiating node.
+        node: NodeId,
+        /// Initiator capabilities.
+        capabilities: BTreeSet<String>,
+    },
+    /// Responder identity and capabilities.
+    HelloAck {
+        /// Responding node.
+        node: NodeId,
+        /// Responder capabilities.
+        capabilities: BTreeSet<String>,
+    },
+    /// Schema versions the initiator supports.
+    SchemaOffer {
+        /// Supported range per schema.
+        schemas: BTreeMap<String, SchemaRange>,
+    },
+    /// Agreed version of every shared schema.
+    SchemaAccept {
+        /// Agreed version per schema.
+        versions: BTreeMap<String, u32>,
+    },
+    /// Initiator key-agreement nonce.
+    KeyShare {
+        /// Initiator nonce.
+        nonce: [u8; DIGEST_LEN],
+    },
+    /// Responder nonce, completing the full handshake.
+    KeyShareAck {
+        /// Responder nonce.
+        nonce: [u8; DIGEST_LEN],
+        /// Ticket for the next connection, if the responder issues them.
+        ticket: Option<IssuedTicket>,
+    },
+    /// Resumption attempt in place of the full handshake.
+    Resume {
+        /// Initiating node.
+        node: NodeId,
+        /// Hash of the initiator's current capability set.
+        capability_hash: [u8; DIGEST_LEN],
+        /// Initiator nonce.
+        nonce: [u8; DIGEST_LEN],
+        /// Ticket from a previous session.
+        ticket: ResumptionTicket,
+        /// Proof of possession of the ticket's resumption secret.
+        binder: [u8; DIGEST_LEN],
+    },
+    /// Resumption accepted.
+    ResumeAccept {
+        /// Responder nonce.
+        nonce: [u8; DIGEST_LEN],
+        /// Replacement ticket, if the responder issues them.
+        ticket: Option<IssuedTicket>,
+    },
+    /// Resumption refused; the initiator continues with [`Self::Hello`].
+    ResumeReject {
+        /// Why the ticket was refused.
+        reason: ResumeRejection,
+    },
+}
+
+impl HandshakeMessage {
+    /// Returns the message name for logs and traces.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Hello { .. } => "Hello",
+            Self::HelloAck { .. } => "HelloAck",
+            Self::SchemaOffer { .. } => "SchemaOffer",
+            Self::SchemaAccept { .. } => "SchemaAccept",
+            Self::KeyShare { .. } => "KeyShare",
+            Self::KeyShareAck { .. } => "KeyShareAck",
+            Self::Resume { .. } => "Resume",
+            Self::ResumeAccept { .. } => "ResumeAccept",
+            Self::ResumeReject { .. } => "ResumeReject",
+        }
+    }
+}
+
+/// A ticket held by the initiator for resuming with one peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredTicket {
+    /// The peer that issued the ticket.
+    pub peer: NodeId,
+    /// The ticket to present.
+    pub ticket: ResumptionTicket,
+    /// Secret received with the ticket.
+    pub secret: AuthKey,
+    /// When the peer stops accepting the ticket.
+    pub expires_at: Time,
+    /// Capabilities negotiated for the ticketed session.
+    pub capabilities: BTreeSet<String>,
+    /// Schema versions negotiated for the ticketed session.
+    pub schema_versions: BTreeMap<String, u32>,
+}
+
+/// Parameters of an established session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstablishedSession {
+    /// The remote node.
+    pub peer: NodeId,
+    /// Capabilities both nodes support.
+    pub capabilities: BTreeSet<String>,
+    /// Agreed version of every shared schema.
+    pub schema_versions: BTreeMap<String, u32>,
+    /// Key for authenticating session traffic.
+    pub session_key: AuthKey,
+    /// Whether the session was resumed from a ticket.
+    pub resumed: bool,
+    /// Ticket for the next connection to `peer`; only set on the initiator.
+    pub ticket: Option<StoredTicket>,
+}
+
+/// Observable outcome of a handshake step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// A session was established.
+    Established {
+        /// The remote node.
+        peer: NodeId,
+        /// Whether a ticket was used.
+        resumed: bool,
+    },
+    /// The responder issued a ticket.
+    TicketIssued {
+        /// The node the ticket was issued to.
+        peer: NodeId,
+        /// Ticket identifier, unique per authority.
+        ticket_id: u64,
+        /// When the ticket expires.
+        expires_at: Time,
+    },
+    /// The responder accepted a ticket.
+    ResumptionAccepted {
+        /// The resuming node.
+        peer: NodeId,
+        /// Identifier of the redeemed ticket.
+        ticket_id: u64,
+    },
+    /// The responder rejected a ticket.
+    ResumptionRejected {
+        /// The node that presented the ticket.
+        peer: NodeId,
+        /// Why the ticket was refused.
+        reason: ResumeRejection,
+    },
+    /// The initiator started the full handshake.
+    FullHandshake {
+        /// Why resumption was not used.
+        reason: FallbackReason,
+    },
+}
+
+/// Error that aborts a handshake.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandshakeError {
+    /// A message arrived out of order.
+    #[error("unexpected handshake message {received}, expected {expected}")]
+    UnexpectedMessage {
+        /// What the state machine was waiting for.
+        expected: &'static str,
+        /// What arrived.
+        received: &'static str,
+    },
+    /// Both nodes speak a schema but share no version of it.
+    #[error("no common version of schema {schema}")]
+    SchemaMismatch {
+        /// The schema without a common version.
+        schema: String,
+    },
+}
+
+/// How long tickets live and how often each may be redeemed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TicketPolicy {
+    /// Time from issue to expiry.
+    pub lifetime: Duration,
+    /// Redemptions allowed per ticket.
+    pub max_uses: u32,
+}
+
+impl TicketPolicy {
+    /// Tickets that may be redeemed once.
+    #[must_use]
+    pub const fn single_use(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            max_uses: 1,
+        }
+    }
+
+    /// Tickets that may be redeemed up to `max_uses` times.
+    #[must_use]
+    pub const fn bounded_reuse(lifetime: Duration, max_uses: u32) -> Self {
+        Self { lifetime, max_uses }
+    }
+}
+
+impl Default for TicketPolicy {
+    fn default() -> Self {
+        Self::single_use(Duration::from_secs(3600))
+    }
+}
+
+/// Issues and redeems resumption tickets for one responder node.
+///
+/// Rotating the ticket key keeps the previous key for verification, so
+/// tickets issued just before a rotation stay valid until
+/// [`retire_key`](Self::retire_key) ends the overlap window.
+#[derive(Debug)]
+pub struct TicketAuthority {
+    keys: KeyRing,
+    epoch: u64,
+    policy: TicketPolicy,
+    next_id: u64,
+    redemptions: BTreeMap<u64, Redemptions>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Redemptions {
+    uses: u32,
+    expires_at: Time,
+}
+
+/// A ticket that passed validation.
+struct Redeemed {
+    body: TicketBody,
+    secret: AuthKey,
+}
+
+impl TicketAuthority {
+    /// Creates an authority that signs tickets with `key`.
+    #[must_use]
+    pub fn new(key: AuthKey, policy: TicketPolicy) -> Self {
+        Self {
+            keys: KeyRing::new(key),
+            epoch: 0,
+            policy,
+            next_id: 0,
+            redemptions: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the ticket policy.
+    #[must_use]
+    pub const fn policy(&self) -> TicketPolicy {
+        self.policy
+    }
+
+    /// Returns the epoch of the active ticket key.
+    #[must_use]
+    pub const fn key_epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Makes `key` the active ticket key, keeping the previous one for
+    /// validating outstanding tickets.
+    pub fn rotate_key(&mut self, key: AuthKey) {
+        self.keys.rotate(key);
+        self.epoch += 1;
+    }
+
+    /// Ends the rotation overlap window; tickets signed with the previous key
+    /// are rejected from now on.
+    pub fn retire_key(&mut self) {
+        self.keys.retire();
+    }
+
+    fn issue(&mut self, now: Time, mut body: TicketBody) -> (IssuedTicket, u64) {
+        let ticket_id = self.next_id;
+        self.next_id += 1;
+        let lifetime = u64::try_from(self.policy.lifetime.as_nanos()).unwrap_or(u64::MAX);
+        body.key_epoch = self.epoch;
+        body.ticket_id = ticket_id;
+        body.issued_at = now;
+        body.expires_at = now.saturating_add_nanos(lifetime);
+
+        let mut bytes = body.encode();
+        let secret = resumption_secret(&self.keys.active, &bytes);
+        let tag = mac(&self.keys.active, &[bytes.as_slice()]);
+        bytes.extend_from_slice(&tag);
+        let issued = IssuedTicket {
+            ticket: ResumptionTicket(bytes),
+            secret,
+            expires_at: body.expires_at,
+        };
+        (issued, ticket_id)
+    }
+
+    fn redeem(
+        &mut self,
+        now: Time,
+        local: &SessionOffer,
+        attempt: &ResumeAttempt<'_>,
+    ) -> Result<Redeemed, ResumeRejection> {
+        let bytes = attempt.ticket.as_bytes();
+        let split = bytes
+            .len()
+            .checked_sub(DIGEST_LEN)
+            .ok_or(ResumeRejection::Malformed)?;
+        let (encoded, tag) = bytes.split_at(split);
+        let body = TicketBody::decode(encoded).ok_or(ResumeRejection::Malformed)?;
+
+        let key = if body.key_epoch == self.epoch {
+            Some(&self.keys.active)
+        } else if body.key_epoch.checked_add(1) == Some(self.epoch) {
+            self.keys.retired.as_ref()
+        } else {
+            None
+        };
+        let key = key.ok_or(ResumeRejection::UnknownKey)?;
+        if !verify(key, &[encoded], tag) {
+            return Err(ResumeRejection::BadAuthenticator);
+        }
+        if now >= body.expires_at {
+            return Err(ResumeRejection::Expired);
+        }
+        if body.initiator != *attempt.node || body.responder != local.node {
+            return Err(ResumeRejection::WrongPeer);
+        }
+        let secret = resumption_secret(key, encoded);
+        if !verify(&secret, &[attempt.nonce, attempt.capability_hash], attempt.binder) {
+            return Err(ResumeRejection::BadBinder);
+        }
+        if body.initiator_capabilities != *attempt.capability_hash
+            || body.responder_capabilities != local.capability_hash()
+        {
+            return Err(ResumeRejection::CapabilitiesChanged);
+        }
+
+        self.redemptions.retain(|_, entry| entry.expires_at > now);
+        let entry = self
+            .redemptions
+            .entry(body.ticket_id)
+            .or_insert(Redemptions {
+                uses: 0,
+                expires_at: body.expires_at,
+            });
+        if entry.uses >= self.policy.max_uses {
+            return Err(ResumeRejection::Replayed);
+        }
+        entry.uses += 1;
+        Ok(Redeemed { body, secret })
+    }
+}
+
+/// Fields of a `Resume` message, borrowed for validation.
+struct ResumeAttempt<'a> {
+    node: &'a NodeId,
+    capability_hash: &'a [u8; DIGEST_LEN],
+    nonce: &'a [u8; DIGEST_LEN],
+    ticket: &'a ResumptionTicket,
+    binder: &'a [u8; DIGEST_LEN],
+}
+
+/// Authenticated contents of a ticket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TicketBody {
+    key_epoch: u64,
+    ticket_id: u64,
+    issued_at: Time,
+    expires_at: Time,
+    initiator: NodeId,
+    responder: NodeId,
+    initiator_capabilities: [u8; DIGEST_LEN],
+    responder_capabilities: [u8; DIGEST_LEN],
+    capabilities: BTreeSet<String>,
+    schema_versions: BTreeMap<String, u32>,
+}
+
+impl TicketBody {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = vec![TICKET_VERSION];
+        out.extend_from_slice(&self.key_epoch.to_be_bytes());
+        out.extend_from_slice(&self.ticket_id.to_be_bytes());
+        out.extend_from_slice(&self.issued_at.as_nanos().to_be_bytes());
+        out.extend_from_slice(&self.expires_at.as_nanos().to_be_bytes());
+        put_str(&mut out, self.initiator.as_str());
+        put_str(&mut out, self.responder.as_str());
+        out.extend_from_slice(&self.initiator_capabilities);
+        out.extend_from_slice(&self.responder_capabilities);
+        put_len(&mut out, self.capabilities.len());
+        for capability in &self.capabilities {
+            put_str(&mut out, capability);
+        }
+        put_len(&mut out, self.schema_versions.len());
+        for (schema, version) in &self.schema_versions {
+            put_str(&mut out, schema);
+            out.extend_from_slice(&version.to_be_bytes());
+        }
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut input = Input(bytes);
+        if input.take::<1>()? != [TICKET_VERSION] {
+            return None;
+        }
+        let key_epoch = u64::from_be_bytes(input.take()?);
+        let ticket_id = u64::from_be_bytes(input.take()?);
+        let issued_at = Time::from_nanos(u64::from_be_bytes(input.take()?));
+        let expires_at = Time::from_nanos(u64::from_be_bytes(input.take()?));
+        let initiator = NodeId::new(input.string()?);
+        let responder = NodeId::new(input.string()?);
+        let initiator_capabilities = input.take()?;
+        let responder_capabilities = input.take()?;
+        let mut capabilities = BTreeSet::new();
+        for _ in 0..input.length()? {
+            capabilities.insert(input.string()?);
+        }
+        let mut schema_versions = BTreeMap::new();
+        for _ in 0..input.length()? {
+            let schema = input.string()?;
+            schema_versions.insert(schema, u32::from_be_bytes(input.take()?));
+        }
+        input.0.is_empty().then_some(Self {
+            key_epoch,
+            ticket_id,
+            issued_at,
+            expires_at,
+            initiator,
+            responder,
+            initiator_capabilities,
+            responder_capabilities,
+            capabilities,
+            schema_versions,
+        })
+    }
+}
+
+fn put_len(out: &mut Vec<u8>, len: usize) {
+    let len = u16::try_from(len).expect("ticket field exceeds u16::MAX");
+    out.extend_from_slice(&len.to_be_bytes());
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    put_len(out, value.len());
+    out.extend_from_slice(value.as_bytes());
+}
+
+struct Input<'a>(&'a [u8]);
+
+impl Input<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.0.split_first_chunk::<N>()?;
+        self.0 = rest;
+        Some(*head)
+    }
+
+    fn length(&mut self) -> Option<usize> {
+        self.take().map(|bytes| usize::from(u16::from_be_bytes(bytes)))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.length()?;
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        String::from_utf8(head.to_vec()).ok()
+    }
+}
+
+fn mac(key: &AuthKey, parts: &[&[u8]]) -> [u8; DIGEST_LEN] {
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+fn verify(key: &AuthKey, parts: &[&[u8]], tag: &[u8]) -> bool {
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(tag).is_ok()
+}
+
+fn resumption_secret(ticket_key: &AuthKey, body: &[u8]) -> AuthKey {
+    ticket_key.derive_subkey(&[RESUMPTION_SECRET_LABEL, body].concat())
+}
+
+fn nonce(rng: &mut DetRng) -> [u8; DIGEST_LEN] {
+    let mut nonce = [0; DIGEST_LEN];
+    rng.fill_bytes(&mut nonce);
+    nonce
+}
+
+fn derive_key(
+    key: &AuthKey,
+    initiator_nonce: &[u8; DIGEST_LEN],
+    responder_nonce: &[u8; DIGEST_LEN],
+    context: &[u8],
+) -> AuthKey {
+    key.derive_with_salt(&[initiator_nonce.as_slice(), responder_nonce].concat(), context)
+}
+
+fn record(events: &mut Vec<SessionEvent>, event: SessionEvent) {
+    if matches!(event, SessionEvent::ResumptionRejected { .. }) {
+        warn!(event = ?event, "session resumption rejected");
+    } else {
+        info!(event = ?event, "session handshake event");
+    }
+    events.push(event);
+}
+
+/// Result of feeding a message to an [`Initiator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitiatorStep {
+    /// Send this message to the responder.
+    Send(HandshakeMessage),
+    /// The session is established; nothing more to send.
+    Established(EstablishedSession),
+}
+
+/// Result of feeding a message to a [`Responder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponderStep {
+    /// Send this message to the initiator.
+    Reply(HandshakeMessage),
+    /// Send `reply`, after which the session is established.
+    Established {
+        /// Final message for the initiator.
+        reply: HandshakeMessage,
+        /// The established session.
+        session: EstablishedSession,
+    },
+}
+
+#[derive(Debug)]
+enum InitiatorState {
+    Idle,
+    AwaitResume {
+        nonce: [u8; DIGEST_LEN],
+        ticket: StoredTicket,
+    },
+    AwaitHelloAck,
+    AwaitSchemaAccept {
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+    },
+    AwaitKeyShareAck {
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+        schema_versions: BTreeMap<String, u32>,
+        nonce: [u8; DIGEST_LEN],
+    },
+    Done,
+}
+
+impl InitiatorState {
+    const fn expects(&self) -> &'static str {
+        match self {
+            Self::Idle => "start",
+            Self::AwaitResume { .. } => "ResumeAccept or ResumeReject",
+            Self::AwaitHelloAck => "HelloAck",
+            Self::AwaitSchemaAccept { .. } => "SchemaAccept",
+            Self::AwaitKeyShareAck { .. } => "KeyShareAck",
+            Self::Done => "nothing",
+        }
+    }
+}
+
+/// The connecting side of a handshake.
+#[derive(Debug)]
+pub struct Initiator {
+    local: SessionOffer,
+    cluster_key: AuthKey,
+    rng: DetRng,
+    ticket: Option<StoredTicket>,
+    state: InitiatorState,
+    events: Vec<SessionEvent>,
+}
+
+impl Initiator {
+    /// Creates an initiator; `cluster_key` is shared by all cluster members.
+    #[must_use]
+    pub fn new(local: SessionOffer, cluster_key: AuthKey, seed: u64) -> Self {
+        Self {
+            local,
+            cluster_key,
+            rng: DetRng::new(seed),
+            ticket: None,
+            state: InitiatorState::Idle,
+            events: Vec::new(),
+        }
+    }
+
+    /// Attempts resumption with `ticket` before falling back to the full
+    /// handshake.
+    #[must_use]
+    pub fn with_ticket(mut self, ticket: StoredTicket) -> Self {
+        self.ticket = Some(ticket);
+        self
+    }
+
+    /// Returns the first message to send.
+    pub fn start(&mut self) -> HandshakeMessage {
+        let Some(ticket) = self.ticket.take() else {
+            return self.fall_back(FallbackReason::NoTicket);
+        };
+        let nonce = nonce(&mut self.rng);
+        let capability_hash = self.local.capability_hash();
+        let binder = mac(&ticket.secret, &[&nonce, &capability_hash]);
+        let message = HandshakeMessage::Resume {
+            node: self.local.node.clone(),
+            capability_hash,
+            nonce,
+            ticket: ticket.ticket.clone(),
+            binder,
+        };
+        self.state = InitiatorState::AwaitResume { nonce, ticket };
+        message
+    }
+
+    /// Handles a message from the responder.
+    pub fn handle(&mut self, message: HandshakeMessage) -> Result<InitiatorStep, HandshakeError> {
+        let state = std::mem::replace(&mut self.state, InitiatorState::Done);
+        match (state, message) {
+            (
+                InitiatorState::AwaitResume { nonce, ticket },
+                HandshakeMessage::ResumeAccept {
+                    nonce: theirs,
+                    ticket: next,
+                },
+            ) => {
+                let session_key = derive_key(&ticket.secret, &nonce, &theirs, RESUMED_KEY_CONTEXT);
+                Ok(self.establish(
+                    ticket.peer,
+                    ticket.capabilities,
+                    ticket.schema_versions,
+                    session_key,
+                    true,
+                    next,
+                ))
+            }
+            (InitiatorState::AwaitResume { .. }, HandshakeMessage::ResumeReject { reason }) => {
+                let hello = self.fall_back(FallbackReason::Rejected(reason));
+                Ok(InitiatorStep::Send(hello))
+            }
+            (InitiatorState::AwaitHelloAck, HandshakeMessage::HelloAck { node, capabilities }) => {
+                let capabilities = self
+                    .local
+                    .capabilities
+                    .intersection(&capabilities)
+                    .cloned()
+                    .collect();
+                self.state = InitiatorState::AwaitSchemaAccept {
+                    peer: node,
+                    capabilities,
+                };
+                Ok(InitiatorStep::Send(HandshakeMessage::SchemaOffer {
+                    schemas: self.local.schemas.clone(),
+                }))
+            }
+            (
+                InitiatorState::AwaitSchemaAccept { peer, capabilities },
+                HandshakeMessage::SchemaAccept { versions },
+            ) => {
+                for (schema, version) in &versions {
+                    let supported = self
+                        .local
+                        .schemas
+                        .get(schema)
+                        .is_some_and(|range| (range.min..=range.max).contains(version));
+                    if !supported {
+                        return Err(HandshakeError::SchemaMismatch {
+                            schema: schema.clone(),
+                        });
+                    }
+                }
+                let nonce = nonce(&mut self.rng);
+                self.state = InitiatorState::AwaitKeyShareAck {
+                    peer,
+                    capabilities,
+                    schema_versions: versions,
+                    nonce,
+                };
+                Ok(InitiatorStep::Send(HandshakeMessage::KeyShare { nonce }))
+            }
+            (
+                InitiatorState::AwaitKeyShareAck {
+                    peer,
+                    capabilities,
+                    schema_versions,
+                    nonce,
+                },
+                HandshakeMessage::KeyShareAck {
+                    nonce: theirs,
+                    ticket,
+                },
+            ) => {
+                let session_key =
+                    derive_key(&self.cluster_key, &nonce, &theirs, SESSION_KEY_CONTEXT);
+                Ok(self.establish(
+                    peer,
+                    capabilities,
+                    schema_versions,
+                    session_key,
+                    false,
+                    ticket,
+                ))
+            }
+            (state, message) => {
+                let expected = state.expects();
+                self.state = state;
+                Err(HandshakeError::UnexpectedMessage {
+                    expected,
+                    received: message.name(),
+                })
+            }
+        }
+    }
+
+    /// Takes the events recorded since the last call.
+    pub fn drain_events(&mut self) -> Vec<SessionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn fall_back(&mut self, reason: FallbackReason) -> HandshakeMessage {
+        record(&mut self.events, SessionEvent::FullHandshake { reason });
+        self.state = InitiatorState::AwaitHelloAck;
+        HandshakeMessage::Hello {
+            node: self.local.node.clone(),
+            capabilities: self.local.capabilities.clone(),
+        }
+    }
+
+    fn establish(
+        &mut self,
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+        schema_versions: BTreeMap<String, u32>,
+        session_key: AuthKey,
+        resumed: bool,
+        issued: Option<IssuedTicket>,
+    ) -> InitiatorStep {
+        let ticket = issued.map(|issued| StoredTicket {
+            peer: peer.clone(),
+            ticket: issued.ticket,
+            secret: issued.secret,
+            expires_at: issued.expires_at,
+            capabilities: capabilities.clone(),
+            schema_versions: schema_versions.clone(),
+        });
+        record(
+            &mut self.events,
+            SessionEvent::Established {
+                peer: peer.clone(),
+                resumed,
+            },
+        );
+        InitiatorStep::Established(EstablishedSession {
+            peer,
+            capabilities,
+            schema_versions,
+            session_key,
+            resumed,
+            ticket,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum ResponderState {
+    Idle,
+    AwaitSchemaOffer {
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+        peer_hash: [u8; DIGEST_LEN],
+    },
+    AwaitKeyShare {
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+        peer_hash: [u8; DIGEST_LEN],
+        schema_versions: BTreeMap<String, u32>,
+    },
+    Done,
+}
+
+impl ResponderState {
+    const fn expects(&self) -> &'static str {
+        match self {
+            Self::Idle => "Hello or Resume",
+            Self::AwaitSchemaOffer { .. } => "SchemaOffer",
+            Self::AwaitKeyShare { .. } => "KeyShare",
+            Self::Done => "nothing",
+        }
+    }
+}
+
+/// The accepting side of a handshake.
+#[derive(Debug)]
+pub struct Responder {
+    local: SessionOffer,
+    cluster_key: AuthKey,
+    rng: DetRng,
+    state: ResponderState,
+    events: Vec<SessionEvent>,
+}
+
+
+impl Responder {
+    /// Creates a responder; `cluster_key` is shared by all cluster members.
+    #[must_use]
+    pub fn new(local: SessionOffer, cluster_key: AuthKey, seed: u64) -> Self {
+        Self {
+            local,
+            cluster_key,
+            rng: DetRng::new(seed),
+            state: ResponderState::Idle,
+            events: Vec::new(),
+        }
+    }
+
+    /// Handles a message from the initiator. Without a ticket authority no
+    /// tickets are issued and resumption attempts are rejected.
+    pub fn handle(
+        &mut self,
+        message: HandshakeMessage,
+        now: Time,
+        tickets: Option<&mut TicketAuthority>,
+    ) -> Result<ResponderStep, HandshakeError> {
+        let state = std::mem::replace(&mut self.state, ResponderState::Done);
+        match (state, message) {
+            (
+                ResponderState::Idle,
+                HandshakeMessage::Resume {
+                    node,
+                    capability_hash,
+                    nonce,
+                    ticket,
+                    binder,
+                },
+            ) => {
+                let attempt = ResumeAttempt {
+                    node: &node,
+                    capability_hash: &capability_hash,
+                    nonce: &nonce,
+                    ticket: &ticket,
+                    binder: &binder,
+                };
+                Ok(self.resume(now, tickets, &attempt))
+            }
+            (ResponderState::Idle, HandshakeMessage::Hello { node, capabilities }) => {
+                let peer_hash = capability_hash(&capabilities);
+                let capabilities = self
+                    .local
+                    .capabilities
+                    .intersection(&capabilities)
+                    .cloned()
+                    .collect();
+                self.state = ResponderState::AwaitSchemaOffer {
+                    peer: node,
+                    capabilities,
+                    peer_hash,
+                };
+                Ok(ResponderStep::Reply(HandshakeMessage::HelloAck {
+                    node: self.local.node.clone(),
+                    capabilities: self.local.capabilities.clone(),
+                }))
+            }
+            (
+                ResponderState::AwaitSchemaOffer {
+                    peer,
+                    capabilities,
+                    peer_hash,
+                },
+                HandshakeMessage::SchemaOffer { schemas },
+            ) => {
+                let mut versions = BTreeMap::new();
+                for (schema, ours) in &self.local.schemas {
+                    let Some(theirs) = schemas.get(schema) else {
+                        continue;
+                    };
+                    let Some(version) = ours.highest_common(*theirs) else {
+                        return Err(HandshakeError::SchemaMismatch {
+                            schema: schema.clone(),
+                        });
+                    };
+                    versions.insert(schema.clone(), version);
+                }
+                self.state = ResponderState::AwaitKeyShare {
+                    peer,
+                    capabilities,
+                    peer_hash,
+                    schema_versions: versions.clone(),
+                };
+                Ok(ResponderStep::Reply(HandshakeMessage::SchemaAccept { versions }))
+            }
+            (
+                ResponderState::AwaitKeyShare {
+                    peer,
+                    capabilities,
+                    peer_hash,
+                    schema_versions,
+                },
+                HandshakeMessage::KeyShare { nonce: theirs },
+            ) => {
+                let nonce = nonce(&mut self.rng);
+                let session_key =
+                    derive_key(&self.cluster_key, &theirs, &nonce, SESSION_KEY_CONTEXT);
+                let ticket = tickets.map(|authority| {
+                    let body = TicketBody {
+                        key_epoch: 0,
+                        ticket_id: 0,
+                        issued_at: now,
+                        expires_at: now,
+                        initiator: peer.clone(),
+                        responder: self.local.node.clone(),
+                        initiator_capabilities: peer_hash,
+                        responder_capabilities: self.local.capability_hash(),
+                        capabilities: capabilities.clone(),
+                        schema_versions: schema_versions.clone(),
+                    };
+                    self.issue(now, authority, body)
+                });
+                let reply = HandshakeMessage::KeyShareAck { nonce, ticket };
+                Ok(self.establish(reply, peer, capabilities, schema_versions, session_key, false))
+            }
+            (state, message) => {
+                let expected = state.expects();
+                self.state = state;
+                Err(HandshakeError::UnexpectedMessage {
+                    expected,
+                    received: message.name(),
+                })
+            }
+        }
+    }
+
+    /// Takes the events recorded since the last call.
+    pub fn drain_events(&mut self) -> Vec<SessionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn resume(
+        &mut self,
+        now: Time,
+        tickets: Option<&mut TicketAuthority>,
+        attempt: &ResumeAttempt<'_>,
+    ) -> ResponderStep {
+        let result = match tickets {
+            Some(authority) => authority
+                .redeem(now, &self.local, attempt)
+                .map(|redeemed| (authority, redeemed)),
+            None => Err(ResumeRejection::Disabled),
+        };
+        let (authority, Redeemed { body, secret }) = match result {
+            Ok(accepted) => accepted,
+            Err(reason) => {
+                let peer = attempt.node.clone();
+                record(&mut self.events, SessionEvent::ResumptionRejected { peer, reason });
+                self.state = ResponderState::Idle;
+                return ResponderStep::Reply(HandshakeMessage::ResumeReject { reason });
+            }
+        };
+        record(
+            &mut self.events,
+            SessionEvent::ResumptionAccepted {
+                peer: body.initiator.clone(),
+                ticket_id: body.ticket_id,
+            },
+        );
+
+        let nonce = nonce(&mut self.rng);
+        let session_key = derive_key(&secret, attempt.nonce, &nonce, RESUMED_KEY_CONTEXT);
+        let ticket = self.issue(now, authority, body.clone());
+        let reply = HandshakeMessage::ResumeAccept {
+            nonce,
+            ticket: Some(ticket),
+        };
+        self.establish(
+            reply,
+            body.initiator,
+            body.capabilities,
+            body.schema_versions,
+            session_key,
+            true,
+        )
+    }
+
+    fn issue(
+        &mut self,
+        now: Time,
+        authority: &mut TicketAuthority,
+        body: TicketBody,
+    ) -> IssuedTicket {
+        let peer = body.initiator.clone();
+        let (issued, ticket_id) = authority.issue(now, body);
+        record(
+            &mut self.events,
+            SessionEvent::TicketIssued {
+                peer,
+                ticket_id,
+                expires_at: issued.expires_at,
+            },
+        );
+        issued
+    }
+
+    fn establish(
+        &mut self,
+        reply: HandshakeMessage,
+        peer: NodeId,
+        capabilities: BTreeSet<String>,
+        schema_versions: BTreeMap<String, u32>,
+        session_key: AuthKey,
+        resumed: bool,
+    ) -> ResponderStep {
+        record(
+            &mut self.events,
+            SessionEvent::Established {
+                peer: peer.clone(),
+                resumed,
+            },
+        );
+        ResponderStep::Established {
+            reply,
+            session: EstablishedSession {
+                peer,
+                capabilities,
+                schema_versions,
+                session_key,
+                resumed,
+                ticket: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::cx::Cx;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::trace::event::{TraceData, TraceEventKind};
+    use crate::types::Budget;
+    use std::future::Future;
+
+    const RECONNECT: &str = "session reconnect";
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn cluster_key() -> AuthKey {
+        AuthKey::from_seed(7)
+    }
+
+    fn ticket_authority(policy: TicketPolicy) -> TicketAuthority {
+        TicketAuthority::new(AuthKey::from_seed(11), policy)
+    }
+
+    fn initiator_offer() -> SessionOffer {
+        SessionOffer::new(NodeId::new("node-a"))
+            .with_capability("obligations")
+            .with_capability("raptorq")
+            .with_schema("region-snapshot", SchemaRange::new(1, 3))
+    }
+
+    fn responder_offer() -> SessionOffer {
+        SessionOffer::new(NodeId::new("node-b"))
+            .with_capability("raptorq")
+            .with_capability("tracing")
+            .with_schema("region-snapshot", SchemaRange::new(2, 4))
+            .with_schema("membership", SchemaRange::new(1, 1))
+    }
+
+    struct Connection {
+        initiator: EstablishedSession,
+        responder: EstablishedSession,
+        initiator_events: Vec<SessionEvent>,
+        responder_events: Vec<SessionEvent>,
+    }
+
+    /// Runs one handshake in memory, tracing every message through `cx`.
+    fn connect(
+        cx: &Cx,
+        local: SessionOffer,
+        ticket: Option<StoredTicket>,
+        authority: &mut TicketAuthority,
+        seed: u64,
+    ) -> Connection {
+        let now = crate::time::wall_now();
+        let mut initiator = Initiator::new(local, cluster_key(), seed);
+        if let Some(ticket) = ticket {
+            initiator = initiator.with_ticket(ticket);
+        }
+        let mut responder = Responder::new(responder_offer(), cluster_key(), seed + 1);
+        let mut message = initiator.start();
+        loop {
+            cx.trace(&format!("session -> {}", message.name()));
+            let step = responder
+                .handle(message, now, Some(&mut *authority))
+                .expect("responder step");
+            let (reply, responder_session) = match step {
+                ResponderStep::Reply(reply) => (reply, None),
+                ResponderStep::Established { reply, session } => (reply, Some(session)),
+            };
+            cx.trace(&format!("session <- {}", reply.name()));
+            match initiator.handle(reply).expect("initiator step") {
+                InitiatorStep::Send(next) => message = next,
+                InitiatorStep::Established(session) => {
+                    return Connection {
+                        initiator: session,
+                        responder: responder_session.expect("responder established"),
+                        initiator_events: initiator.drain_events(),
+                        responder_events: responder.drain_events(),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Runs `future` as a lab task and returns its output with the handshake
+    /// messages it traced, split at each reconnect marker.
+    fn run_in_lab<F, T>(future: F) -> (T, Vec<Vec<String>>)
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut lab = LabRuntime::new(LabConfig::new(29).with_auto_advance());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (task, mut handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, future)
+            .expect("create handshake task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_with_auto_advance();
+        let output = handle
+            .try_join()
+            .expect("join handshake task")
+            .expect("handshake task completed");
+
+        let mut connections = vec![Vec::new()];
+        for event in lab.trace().snapshot() {
+            if event.kind != TraceEventKind::UserTrace {
+                continue;
+            }
+            match &event.data {
+                TraceData::Message(message) if message == RECONNECT => {
+                    connections.push(Vec::new());
+                }
+                TraceData::Message(message) if message.starts_with("session ") => {
+                    let name = message.rsplit(' ').next().unwrap_or_default();
+                    connections.last_mut().expect("segment").push(name.to_string());
+                }
+                _ => {}
+            }
+        }
+        (output, connections)
+    }
+
+    fn rejections(events: &[SessionEvent]) -> Vec<ResumeRejection> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                SessionEvent::ResumptionRejected { reason, .. } => Some(*reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const FULL: [&str; 6] = [
+        "Hello",
+        "HelloAck",
+        "SchemaOffer",
+        "SchemaAccept",
+        "KeyShare",
+        "KeyShareAck",
+    ];
+
+    #[test]
+    fn resumed_reconnect_skips_negotiation() {
+        init_test("resumed_reconnect_skips_negotiation");
+        let ((first, second), trace) = run_in_lab(async {
+            let cx = Cx::current().expect("task Cx");
+            let mut authority = ticket_authority(TicketPolicy::default());
+            let first = connect(&cx, initiator_offer(), None, &mut authority, 1);
+            cx.trace(RECONNECT);
+            let ticket = first.initiator.ticket.clone().expect("ticket issued");
+            let second = connect(&cx, initiator_offer(), Some(ticket), &mut authority, 2);
+            (first, second)
+        });
+
+        crate::assert_with_log!(trace[0] == FULL, "full handshake", FULL, trace[0]);
+        let resumed = ["Resume", "ResumeAccept"];
+        crate::assert_with_log!(trace[1] == resumed, "resumed", resumed, trace[1]);
+
+        let capabilities: BTreeSet<String> = ["raptorq".to_string()].into();
+        let versions: BTreeMap<String, u32> = [("region-snapshot".to_string(), 3)].into();
+        for session in [&first.initiator, &first.responder, &second.initiator, &second.responder] {
+            crate::assert_with_log!(
+                session.capabilities == capabilities,
+                "capabilities",
+                capabilities,
+                session.capabilities
+            );
+            crate::assert_with_log!(
+                session.schema_versions == versions,
+                "schema versions",
+                versions,
+                session.schema_versions
+            );
+        }
+        let keys_agree = first.initiator.session_key == first.responder.session_key
+            && second.initiator.session_key == second.responder.session_key;
+        crate::assert_with_log!(keys_agree, "both sides derive the same key", true, keys_agree);
+        let fresh = second.initiator.session_key != first.initiator.session_key;
+        crate::assert_with_log!(fresh, "resumed key differs", true, fresh);
+        let is_resumed = second.initiator.resumed;
+        crate::assert_with_log!(is_resumed, "resumed", true, is_resumed);
+        let replaced = second.initiator.ticket.is_some();
+        crate::assert_with_log!(replaced, "replacement ticket", true, replaced);
+
+        let fallback = &first.initiator_events[0];
+        let expected = SessionEvent::FullHandshake {
+            reason: FallbackReason::NoTicket,
+        };
+        crate::assert_with_log!(*fallback == expected, "no ticket", expected, fallback);
+        let accepted = second
+            .responder_events
+            .iter()
+            .any(|event| matches!(event, SessionEvent::ResumptionAccepted { ticket_id: 0, .. }));
+        crate::assert_with_log!(accepted, "accepted event", true, second.responder_events);
+        crate::test_complete!("resumed_reconnect_skips_negotiation");
+    }
+
+    #[test]
+    fn capability_change_forces_full_handshake() {
+        init_test("capability_change_forces_full_handshake");
+        let ((_, second), trace) = run_in_lab(async {
+            let cx = Cx::current().expect("task Cx");
+            let mut authority = ticket_authority(TicketPolicy::default());
+            let first = connect(&cx, initiator_offer(), None, &mut authority, 1);
+            cx.trace(RECONNECT);
+            let ticket = first.initiator.ticket.clone().expect("ticket issued");
+            let upgraded = initiator_offer().with_capability("tracing");
+            let second = connect(&cx, upgraded, Some(ticket), &mut authority, 2);
+            (first, second)
+        });
+
+        let mut expected = vec!["Resume".to_string(), "ResumeReject".to_string()];
+        expected.extend(FULL.iter().map(ToString::to_string));
+        crate::assert_with_log!(trace[1] == expected, "fallback", expected, trace[1]);
+        let reasons = rejections(&second.responder_events);
+        let changed = vec![ResumeRejection::CapabilitiesChanged];
+        crate::assert_with_log!(reasons == changed, "reason", changed, reasons);
+        let fallback = SessionEvent::FullHandshake {
+            reason: FallbackReason::Rejected(ResumeRejection::CapabilitiesChanged),
+        };
+        let fell_back = second.initiator_events.contains(&fallback);
+        crate::assert_with_log!(fell_back, "fallback event", true, second.initiator_events);
+
+        let capabilities: BTreeSet<String> = ["raptorq".to_string(), "tracing".to_string()].into();
+        crate::assert_with_log!(
+            second.initiator.capabilities == capabilities,
+            "renegotiated capabilities",
+            capabilities,
+            second.initiator.capabilities
+        );
+        let resumed = second.initiator.resumed;
+        crate::assert_with_log!(!resumed, "full handshake", false, resumed);
+        crate::test_complete!("capability_change_forces_full_handshake");
+    }
+
+    #[test]
+    fn expired_and_replayed_tickets_are_rejected() {
+        init_test("expired_and_replayed_tickets_are_rejected");
+        let (reasons, trace) = run_in_lab(async {
+            let cx = Cx::current().expect("task Cx");
+            let policy = TicketPolicy::single_use(Duration::from_secs(10));
+            let mut authority = ticket_authority(policy);
+            let first = connect(&cx, initiator_offer(), None, &mut authority, 1);
+            let original = first.initiator.ticket.expect("ticket issued");
+
+            cx.trace(RECONNECT);
+            let second = connect(&cx, initiator_offer(), Some(original.clone()), &mut authority, 2);
+            let replacement = second.initiator.ticket.expect("replacement ticket");
+
+            cx.trace(RECONNECT);
+            let replayed = connect(&cx, initiator_offer(), Some(original), &mut authority, 3);
+
+            crate::time::sleep(crate::time::wall_now(), Duration::from_secs(11)).await;
+            cx.trace(RECONNECT);
+            let expired = connect(&cx, initiator_offer(), Some(replacement), &mut authority, 4);
+
+            let mut reasons = rejections(&replayed.responder_events);
+            reasons.extend(rejections(&expired.responder_events));
+            reasons
+        });
+
+        let resumed = ["Resume", "ResumeAccept"];
+        crate::assert_with_log!(trace[1] == resumed, "first use", resumed, trace[1]);
+        for attempt in &trace[2..] {
+            let rejected = attempt[..2] == ["Resume", "ResumeReject"] && attempt[2..] == FULL;
+            crate::assert_with_log!(rejected, "rejected then full", true, attempt);
+        }
+        let expected = vec![ResumeRejection::Replayed, ResumeRejection::Expired];
+        crate::assert_with_log!(reasons == expected, "distinct reasons", expected, reasons);
+        crate::test_complete!("expired_and_replayed_tickets_are_rejected");
+    }
+
+    #[test]
+    fn key_rotation_keeps_resumption_during_overlap() {
+        init_test("key_rotation_keeps_resumption_during_overlap");
+        let (outcomes, _) = run_in_lab(async {
+            let cx = Cx::current().expect("task Cx");
+            let policy = TicketPolicy::bounded_reuse(Duration::from_secs(3600), 4);
+            let mut authority = ticket_authority(policy);
+            let first = connect(&cx, initiator_offer(), None, &mut authority, 1);
+            let stale = first.initiator.ticket.expect("ticket issued");
+
+            authority.rotate_key(AuthKey::from_seed(12));
+            let overlap = connect(&cx, initiator_offer(), Some(stale.clone()), &mut authority, 2);
+            let fresh = overlap.initiator.ticket.clone().expect("replacement ticket");
+            let reused = connect(&cx, initiator_offer(), Some(stale.clone()), &mut authority, 3);
+
+            authority.retire_key();
+            let retired = connect(&cx, initiator_offer(), Some(stale), &mut authority, 4);
+            let current = connect(&cx, initiator_offer(), Some(fresh), &mut authority, 5);
+
+            [overlap, reused, retired, current]
+                .into_iter()
+                .map(|connection| {
+                    (connection.initiator.resumed, rejections(&connection.responder_events))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let expected = vec![
+            (true, vec![]),
+            (true, vec![]),
+            (false, vec![ResumeRejection::UnknownKey]),
+            (true, vec![]),
+        ];
+        crate::assert_with_log!(outcomes == expected, "rotation", expected, outcomes);
+        crate::test_complete!("key_rotation_keeps_resumption_during_overlap");
+    }
+
+    #[test]
+    fn forged_and_misdirected_tickets_are_rejected() {
+        init_test("forged_and_misdirected_tickets_are_rejected");
+        let (reasons, _) = run_in_lab(async {
+            let cx = Cx::current().expect("task Cx");
+            let mut authority = ticket_authority(TicketPolicy::default());
+            let first = connect(&cx, initiator_offer(), None, &mut authority, 1);
+            let ticket = first.initiator.ticket.expect("ticket issued");
+
+            let mut tampered = ticket.clone();
+            let mut bytes = tampered.ticket.as_bytes().to_vec();
+            bytes[12] ^= 1;
+            tampered.ticket = ResumptionTicket::from_bytes(bytes);
+
+            let mut truncated = ticket.clone();
+            truncated.ticket = ResumptionTicket::from_bytes(ticket.ticket.as_bytes()[..8].to_vec());
+
+            let mut stolen = ticket.clone();
+            stolen.secret = AuthKey::from_seed(99);
+
+            let mut other = initiator_offer();
+            other.node = NodeId::new("node-c");
+
+            let attempts = [
+                (initiator_offer(), tampered),
+                (initiator_offer(), truncated),
+                (initiator_offer(), stolen),
+                (other, ticket),
+            ];
+            let mut reasons = Vec::new();
+            for (seed, (offer, ticket)) in (2..).zip(attempts) {
+                let connection = connect(&cx, offer, Some(ticket), &mut authority, seed);
+                reasons.extend(rejections(&connection.responder_events));
+            }
+            reasons
+        });
+
+        let expected = vec![
+            ResumeRejection::BadAuthenticator,
+            ResumeRejection::Malformed,
+            ResumeRejection::BadBinder,
+            ResumeRejection::WrongPeer,
+        ];
+        crate::assert_with_log!(reasons == expected, "reasons", expected, reasons);
+        crate::test_complete!("forged_and_misdirected_tickets_are_rejected");
+    }
+
+    #[test]
+    fn responder_without_authority_rejects_and_schema_mismatch_fails() {
+        init_test("responder_without_authority_rejects_and_schema_mismatch_fails");
+        let now = Time::from_secs(1);
+        let mut authority = ticket_authority(TicketPolicy::default());
+        let mut initiator = Initiator::new(initiator_offer(), cluster_key(), 1);
+        let mut responder = Responder::new(responder_offer(), cluster_key(), 2);
+        let mut message = initiator.start();
+        let ticket = loop {
+            let reply = match responder.handle(message, now, Some(&mut authority)) {
+                Ok(ResponderStep::Reply(reply) | ResponderStep::Established { reply, .. }) => reply,
+                Err(error) => panic!("handshake failed: {error}"),
+            };
+            match initiator.handle(reply).expect("initiator step") {
+                InitiatorStep::Send(next) => message = next,
+                InitiatorStep::Established(session) => break session.ticket,
+            }
+        };
+
+        let mut initiator = Initiator::new(initiator_offer(), cluster_key(), 3)
+            .with_ticket(ticket.expect("ticket issued"));
+        let mut responder = Responder::new(responder_offer(), cluster_key(), 4);
+        let step = responder.handle(initiator.start(), now, None).expect("reply");
+        let expected = ResponderStep::Reply(HandshakeMessage::ResumeReject {
+            reason: ResumeRejection::Disabled,
+        });
+        crate::assert_with_log!(step == expected, "disabled", expected, step);
+
+        let incompatible = SessionOffer::new(NodeId::new("node-a"))
+            .with_schema("region-snapshot", SchemaRange::new(5, 6));
+        let mut initiator = Initiator::new(incompatible, cluster_key(), 5);
+        let mut responder = Responder::new(responder_offer(), cluster_key(), 6);
+        let hello = initiator.start();
+        let Ok(ResponderStep::Reply(ack)) = responder.handle(hello, now, None) else {
+            panic!("expected HelloAck");
+        };
+        let Ok(InitiatorStep::Send(offer)) = initiator.handle(ack) else {
+            panic!("expected SchemaOffer");
+        };
+        let result = responder.handle(offer, now, None);
+        let mismatch = matches!(
+            result,
+            Err(HandshakeError::SchemaMismatch { ref schema }) if schema == "region-snapshot"
+        );
+        crate::assert_with_log!(mismatch, "schema mismatch", "SchemaMismatch", result);
+
+        let result = initiator.handle(HandshakeMessage::KeyShare { nonce: [0; 32] });
+        let unexpected = matches!(result, Err(HandshakeError::UnexpectedMessage { .. }));
+        crate::assert_with_log!(unexpected, "out of order", "UnexpectedMessage", result);
+        crate::test_complete!("responder_without_authority_rejects_and_schema_mismatch_fails");
+    }
+}
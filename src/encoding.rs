@@ -4,13 +4,18 @@
 //! bytes into source symbols and produces a configurable number of repair
 //! symbols per block. Repair symbols are generated via the systematic
 //! RaptorQ encoder (precode + LT) for deterministic RFC-6330-style behavior.
+//! Under CPU pressure the repair rate can adapt at runtime; see [`adaptive`].
 
 use crate::config::EncodingConfig;
+use crate::encoding::adaptive::{RepairAdaptation, RepairRate};
 use crate::error::{Error, ErrorKind};
 use crate::raptorq::systematic::{SystematicEncoder, SystematicParamError, SystematicParams};
 use crate::types::resource::{PoolExhausted, SymbolPool};
 use crate::types::{ObjectId, Symbol, SymbolId, SymbolKind};
 use std::cmp::min;
+use std::time::Duration;
+
+pub mod adaptive;
 
 /// The symbol ID format caps objects at 256 source blocks.
 pub(crate) const MAX_SOURCE_BLOCKS: usize = u8::MAX as usize + 1;
@@ -145,6 +150,7 @@ pub struct EncodingPipeline {
     config: EncodingConfig,
     pool: SymbolPool,
     stats: EncodingStats,
+    adaptation: Option<RepairAdaptation>,
 }
 
 impl EncodingPipeline {
@@ -156,9 +162,43 @@ impl EncodingPipeline {
             config,
             pool,
             stats: EncodingStats::default(),
+            adaptation: None,
         }
     }
 
+    /// Adapts the repair rate to CPU pressure and receiver loss.
+    ///
+    /// The adaptation replaces the configured `repair_overhead` for
+    /// [`Self::encode`]; explicit repair counts are left alone.
+    #[must_use]
+    pub fn with_adaptive_repair(mut self, adaptation: RepairAdaptation) -> Self {
+        self.adaptation = Some(adaptation);
+        self
+    }
+
+    /// Returns the repair rate adaptation, if enabled.
+    #[must_use]
+    pub const fn adaptive_repair(&self) -> Option<&RepairAdaptation> {
+        self.adaptation.as_ref()
+    }
+
+    /// Returns the repair rate adaptation mutably, for feeding receiver loss.
+    pub const fn adaptive_repair_mut(&mut self) -> Option<&mut RepairAdaptation> {
+        self.adaptation.as_mut()
+    }
+
+    /// Returns the repair rate [`Self::encode`] currently uses.
+    #[must_use]
+    pub fn repair_rate(&self) -> RepairRate {
+        self.adaptation.as_ref().map_or(
+            RepairRate {
+                overhead: self.config.repair_overhead,
+                repair_interval: Duration::ZERO,
+            },
+            RepairAdaptation::rate,
+        )
+    }
+
     /// Returns encoding statistics for the most recent run.
     #[must_use]
     #[inline]
@@ -172,8 +212,12 @@ impl EncodingPipeline {
         self.stats = EncodingStats::default();
     }
 
-    /// Encodes data using the configured repair overhead.
+    /// Encodes data using the configured repair overhead, or the adapted one
+    /// when adaptive repair is enabled.
     pub fn encode<'a>(&'a mut self, object_id: ObjectId, data: &'a [u8]) -> EncodingIterator<'a> {
+        if let Some(adaptation) = &mut self.adaptation {
+            adaptation.observe();
+        }
         self.encode_internal(object_id, data, None)
     }
 
@@ -526,7 +570,7 @@ impl Iterator for EncodingIterator<'_> {
             }
 
             let repair = u32::try_from(self.repair_override.unwrap_or_else(|| {
-                compute_repair_count(block.k, self.pipeline.config.repair_overhead)
+                compute_repair_count(block.k, self.pipeline.repair_rate().overhead)
             }))
             .unwrap_or(u32::MAX);
             let total = k.saturating_add(repair);
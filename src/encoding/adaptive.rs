@@ -0,0 +1,801 @@
+//! Adaptive repair-symbol production under CPU pressure.
+//!
+//! Repair symbols cost CPU. On a saturated host, producing them at a fixed
+//! overhead makes the saturation worse, which raises loss, which calls for
+//! more repair. [`RepairAdaptation`] breaks that loop by scaling the repair
+//! overhead factor and the pacing between repair symbols from two signals:
+//!
+//! - **CPU pressure**, read from a shared [`SystemPressure`] or an injected
+//!   callback ([`PressureSignal`]). Pressure moves a throttle level by at most
+//!   one step per observation, with hysteresis on the way back down, and each
+//!   level maps to an overhead ceiling and a repair interval between the
+//!   configured bounds.
+//! - **Receiver feedback**, as ACK counts or a transport
+//!   [`PathQualitySnapshot`]. The observed loss rate sets the overhead the
+//!   receiver actually needs, so low loss saves repair even without pressure.
+//!
+//! When loss demands more repair than the pressure ceiling allows, the
+//! configured [`ConflictPolicy`] decides which signal wins. Pacing always
+//! follows pressure.
+//!
+//! Adaptation only happens in [`RepairAdaptation::observe`], which the
+//! pipeline calls at the start of every [`encode`](super::EncodingPipeline::encode).
+//! Given the same pressure readings and feedback in the same order, it makes
+//! the same decisions, so lab runs with scripted pressure are reproducible.
+//! Every change is recorded as a [`RepairAdaptationEvent`] and logged at info
+//! level under this module's target.
+
+use super::EncodingError;
+use crate::distributed::PathQualitySnapshot;
+use crate::types::SystemPressure;
+use crate::types::pressure::sanitise_headroom;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where [`RepairAdaptation`] reads CPU headroom from.
+#[derive(Clone)]
+pub enum PressureSignal {
+    /// A shared pressure handle, e.g. from the runtime resource monitor.
+    System(Arc<SystemPressure>),
+    /// A callback returning headroom in `0.0..=1.0`, for standalone use.
+    Callback(Arc<dyn Fn() -> f32 + Send + Sync>),
+}
+
+impl PressureSignal {
+    /// Wraps a headroom callback.
+    pub fn callback(headroom: impl Fn() -> f32 + Send + Sync + 'static) -> Self {
+        Self::Callback(Arc::new(headroom))
+    }
+
+    /// Reads the current headroom. Out-of-range and NaN readings are
+    /// sanitised the same way [`SystemPressure`] does.
+    #[must_use]
+    pub fn headroom(&self) -> f32 {
+        match self {
+            Self::System(pressure) => pressure.headroom(),
+            Self::Callback(headroom) => sanitise_headroom(headroom()),
+        }
+    }
+}
+
+impl fmt::Debug for PressureSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System(pressure) => f.debug_tuple("System").field(pressure).finish(),
+            Self::Callback(_) => f.debug_tuple("Callback").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Which signal wins when receiver loss needs more repair than the pressure
+/// ceiling allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Produce the repair the observed loss needs, even above the pressure
+    /// ceiling; only pacing backs off. Favors delivery over CPU.
+    #[default]
+    ProtectDelivery,
+    /// Cap repair at the pressure ceiling regardless of loss. Favors the host
+    /// over delivery.
+    ShedLoad,
+}
+
+/// Bounds and tuning for [`RepairAdaptation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveRepairConfig {
+    /// Lowest repair overhead factor, used at the highest throttle level.
+    pub min_overhead: f64,
+    /// Highest repair overhead factor, used with no pressure.
+    pub max_overhead: f64,
+    /// Repair interval with no pressure.
+    pub min_repair_interval: Duration,
+    /// Repair interval at the highest throttle level.
+    pub max_repair_interval: Duration,
+    /// Number of throttle levels above "no pressure".
+    pub levels: u8,
+    /// How far pressure must fall below a level's entry threshold before
+    /// the level is left, in permille of pressure.
+    pub hysteresis_permille: u16,
+    /// Overhead added on top of what the observed loss strictly needs.
+    pub loss_margin: f64,
+    /// Resolution between loss and pressure.
+    pub policy: ConflictPolicy,
+}
+
+impl Default for AdaptiveRepairConfig {
+    fn default() -> Self {
+        Self {
+            min_overhead: 1.02,
+            max_overhead: 1.25,
+            min_repair_interval: Duration::ZERO,
+            max_repair_interval: Duration::from_millis(1),
+            levels: 4,
+            hysteresis_permille: 100,
+            loss_margin: 0.05,
+            policy: ConflictPolicy::ProtectDelivery,
+        }
+    }
+}
+
+impl AdaptiveRepairConfig {
+    /// Checks that the bounds are consistent.
+    pub fn validate(&self) -> Result<(), EncodingError> {
+        let invalid = |reason: &str| {
+            Err(EncodingError::InvalidConfig {
+                reason: reason.to_string(),
+            })
+        };
+        if !self.min_overhead.is_finite() || self.min_overhead < 1.0 {
+            return invalid("adaptive min_overhead must be finite and >= 1.0");
+        }
+        if !self.max_overhead.is_finite() || self.max_overhead < self.min_overhead {
+            return invalid("adaptive max_overhead must be finite and >= min_overhead");
+        }
+        if self.min_repair_interval > self.max_repair_interval {
+            return invalid("adaptive min_repair_interval must not exceed max_repair_interval");
+        }
+        if self.levels == 0 {
+            return invalid("adaptive levels must be non-zero");
+        }
+        if self.hysteresis_permille >= 1000 {
+            return invalid("adaptive hysteresis_permille must be below 1000");
+        }
+        if !self.loss_margin.is_finite() || self.loss_margin < 0.0 {
+            return invalid("adaptive loss_margin must be finite and >= 0.0");
+        }
+        Ok(())
+    }
+}
+
+/// Repair production rate: how many repair symbols, and how fast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepairRate {
+    /// Repair overhead factor (e.g. 1.2 = 20% repair symbols).
+    pub overhead: f64,
+    /// Suggested pause between repair symbols for transports that pace them.
+    pub repair_interval: Duration,
+}
+
+/// What triggered an adaptation step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdaptationCause {
+    /// The throttle level moved.
+    Pressure,
+    /// Receiver feedback changed the loss-driven overhead.
+    Loss,
+    /// Both in the same observation.
+    PressureAndLoss,
+}
+
+impl AdaptationCause {
+    /// Returns the stable name used in logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pressure => "pressure",
+            Self::Loss => "loss",
+            Self::PressureAndLoss => "pressure_and_loss",
+        }
+    }
+}
+
+/// One adaptation step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairAdaptationEvent {
+    /// Observation count at which the step happened, starting at 1.
+    pub observation: u64,
+    /// What triggered it.
+    pub cause: AdaptationCause,
+    /// The headroom reading taken by this observation.
+    pub headroom: f32,
+    /// Loss rate from receiver feedback, in permille, if any.
+    pub loss_permille: Option<u16>,
+    /// Throttle level before the step.
+    pub from_level: u8,
+    /// Throttle level after the step.
+    pub to_level: u8,
+    /// Rate before the step.
+    pub from: RepairRate,
+    /// Rate after the step.
+    pub to: RepairRate,
+}
+
+impl RepairAdaptationEvent {
+    fn emit(&self) {
+        crate::tracing_compat::info!(
+            cause = self.cause.as_str(),
+            observation = self.observation,
+            headroom = self.headroom,
+            loss_permille = ?self.loss_permille,
+            from_level = self.from_level,
+            to_level = self.to_level,
+            from_overhead = self.from.overhead,
+            to_overhead = self.to.overhead,
+            from_interval_ns = self.from.repair_interval.as_nanos(),
+            to_interval_ns = self.to.repair_interval.as_nanos(),
+            "repair rate adapted"
+        );
+    }
+}
+
+/// Pressure- and loss-driven repair rate controller.
+///
+/// Attach it with [`EncodingPipeline::with_adaptive_repair`]; it can also be
+/// driven directly by a transport that generates repair on its own schedule.
+///
+/// [`EncodingPipeline::with_adaptive_repair`]: super::EncodingPipeline::with_adaptive_repair
+#[derive(Debug)]
+pub struct RepairAdaptation {
+    config: AdaptiveRepairConfig,
+    signal: PressureSignal,
+    level: u8,
+    /// Latest feedback, applied by the next observation.
+    loss_permille: Option<u16>,
+    applied_loss_permille: Option<u16>,
+    rate: RepairRate,
+    observations: u64,
+    events: Vec<RepairAdaptationEvent>,
+}
+
+impl RepairAdaptation {
+    /// Creates a controller at throttle level 0 with no loss feedback.
+    pub fn new(
+        config: AdaptiveRepairConfig,
+        signal: PressureSignal,
+    ) -> Result<Self, EncodingError> {
+        config.validate()?;
+        let mut adaptation = Self {
+            config,
+            signal,
+            level: 0,
+            loss_permille: None,
+            applied_loss_permille: None,
+            rate: RepairRate {
+                overhead: 1.0,
+                repair_interval: Duration::ZERO,
+            },
+            observations: 0,
+            events: Vec::new(),
+        };
+        adaptation.rate = adaptation.rate_for(0, None);
+        Ok(adaptation)
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &AdaptiveRepairConfig {
+        &self.config
+    }
+
+    /// Returns the current throttle level (0 = no pressure).
+    #[must_use]
+    pub const fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Returns the current repair rate.
+    #[must_use]
+    pub const fn rate(&self) -> RepairRate {
+        self.rate
+    }
+
+    /// Returns the latest receiver loss rate, in permille.
+    #[must_use]
+    pub const fn loss_permille(&self) -> Option<u16> {
+        self.loss_permille
+    }
+
+    /// Records receiver ACK counts since the last report. Ignored when both
+    /// counts are zero.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn record_acks(&mut self, delivered: u64, lost: u64) {
+        let total = u128::from(delivered) + u128::from(lost);
+        if total > 0 {
+            let permille = (u128::from(lost) * 1000 + total / 2) / total;
+            self.loss_permille = Some(permille.min(1000) as u16);
+        }
+    }
+
+    /// Records a transport path-quality snapshot as loss feedback.
+    pub fn record_path_quality(&mut self, snapshot: PathQualitySnapshot) {
+        self.loss_permille = Some(snapshot.loss_ewma_permille.min(1000));
+    }
+
+    /// Reads the pressure signal, applies pending feedback and moves the rate.
+    ///
+    /// Returns the adaptation step, if the rate or level changed.
+    pub fn observe(&mut self) -> Option<RepairAdaptationEvent> {
+        self.observations += 1;
+        let headroom = self.signal.headroom();
+        let level = self.next_level(headroom);
+        let loss = self.loss_permille;
+        let rate = self.rate_for(level, loss);
+
+        let pressure_moved = level != self.level;
+        let without_new_loss = self.rate_for(level, self.applied_loss_permille);
+        let loss_moved = rate.overhead.to_bits() != without_new_loss.overhead.to_bits();
+        self.applied_loss_permille = loss;
+        if !pressure_moved && rate == self.rate {
+            return None;
+        }
+
+        let cause = match (pressure_moved, loss_moved) {
+            (true, true) => AdaptationCause::PressureAndLoss,
+            (true, false) => AdaptationCause::Pressure,
+            (false, _) => AdaptationCause::Loss,
+        };
+        let event = RepairAdaptationEvent {
+            observation: self.observations,
+            cause,
+            headroom,
+            loss_permille: loss,
+            from_level: self.level,
+            to_level: level,
+            from: self.rate,
+            to: rate,
+        };
+        self.level = level;
+        self.rate = rate;
+        event.emit();
+        self.events.push(event.clone());
+        Some(event)
+    }
+
+    /// Returns the adaptation steps recorded so far.
+    #[must_use]
+    pub fn events(&self) -> &[RepairAdaptationEvent] {
+        &self.events
+    }
+
+    /// Drains the recorded adaptation steps.
+    pub fn take_events(&mut self) -> Vec<RepairAdaptationEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Pressure in permille at which `level` is entered.
+    fn entry_permille(&self, level: u8) -> u32 {
+        u32::from(level) * 1000 / (u32::from(self.config.levels) + 1)
+    }
+
+    /// Moves at most one level towards the pressure implied by `headroom`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn next_level(&self, headroom: f32) -> u8 {
+        let pressure = ((1.0 - headroom) * 1000.0).round() as u32;
+        let hysteresis = u32::from(self.config.hysteresis_permille);
+        if self.level < self.config.levels && pressure >= self.entry_permille(self.level + 1) {
+            self.level + 1
+        } else if self.level > 0 && pressure + hysteresis < self.entry_permille(self.level) {
+            self.level - 1
+        } else {
+            self.level
+        }
+    }
+
+    fn rate_for(&self, level: u8, loss_permille: Option<u16>) -> RepairRate {
+        let config = &self.config;
+        let levels = u32::from(config.levels);
+        let level = u32::from(level).min(levels);
+        // Pin the endpoints so float rounding never leaves the bounds.
+        let ceiling = if level == levels {
+            config.min_overhead
+        } else {
+            let span = config.max_overhead - config.min_overhead;
+            (config.max_overhead - span * f64::from(level) / f64::from(levels))
+                .max(config.min_overhead)
+        };
+        let interval_span = config.max_repair_interval - config.min_repair_interval;
+        let repair_interval = config.min_repair_interval + interval_span * level / levels;
+
+        let overhead = match loss_permille.map(|loss| self.loss_demand(loss)) {
+            None => ceiling,
+            Some(demand) if demand <= ceiling => demand,
+            Some(demand) => match config.policy {
+                ConflictPolicy::ProtectDelivery => demand,
+                ConflictPolicy::ShedLoad => ceiling,
+            },
+        };
+        RepairRate {
+            overhead,
+            repair_interval,
+        }
+    }
+
+    /// Overhead needed to deliver `K` symbols through `loss_permille` loss,
+    /// plus the margin, within the configured bounds.
+    fn loss_demand(&self, loss_permille: u16) -> f64 {
+        let config = &self.config;
+        let delivered = 1.0 - f64::from(loss_permille.min(1000)) / 1000.0;
+        if delivered <= 0.0 {
+            return config.max_overhead;
+        }
+        (1.0 / delivered + config.loss_margin).clamp(config.min_overhead, config.max_overhead)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::config::EncodingConfig;
+    use crate::decoding::{DecodingConfig, DecodingPipeline};
+    use crate::encoding::EncodingPipeline;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::security::AuthenticatedSymbol;
+    use crate::types::resource::{PoolConfig, SymbolPool};
+    use crate::types::{Budget, ObjectId, ObjectParams};
+    use parking_lot::Mutex;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn scripted(config: AdaptiveRepairConfig) -> (Arc<SystemPressure>, RepairAdaptation) {
+        let pressure = Arc::new(SystemPressure::new());
+        let signal = PressureSignal::System(Arc::clone(&pressure));
+        let adaptation = RepairAdaptation::new(config, signal).expect("valid config");
+        (pressure, adaptation)
+    }
+
+    fn assert_within_bounds(config: &AdaptiveRepairConfig, rate: RepairRate) {
+        let in_bounds = rate.overhead >= config.min_overhead
+            && rate.overhead <= config.max_overhead
+            && rate.repair_interval >= config.min_repair_interval
+            && rate.repair_interval <= config.max_repair_interval;
+        crate::assert_with_log!(in_bounds, "rate within bounds", config, rate);
+    }
+
+    #[test]
+    fn pressure_ramp_steps_levels_with_hysteresis() {
+        init_test("pressure_ramp_steps_levels_with_hysteresis");
+        let config = AdaptiveRepairConfig::default();
+        let (pressure, mut adaptation) = scripted(config.clone());
+
+        // Entry thresholds are 200/400/600/800 permille of pressure; leaving
+        // a level needs 100 permille below its entry threshold.
+        let ramp = [1.0, 0.7, 0.5, 0.3, 0.3, 0.35, 0.55, 0.55, 0.65, 0.75, 1.0];
+        let mut levels = Vec::new();
+        for headroom in ramp {
+            pressure.set_headroom(headroom);
+            adaptation.observe();
+            levels.push(adaptation.level());
+            assert_within_bounds(&config, adaptation.rate());
+        }
+        let expected = vec![0, 1, 2, 3, 3, 3, 2, 2, 2, 1, 0];
+        crate::assert_with_log!(levels == expected, "levels", expected, levels);
+
+        let events = adaptation.take_events();
+        let steps: Vec<_> = events
+            .iter()
+            .map(|event| (event.observation, event.from_level, event.to_level))
+            .collect();
+        let expected_steps = vec![
+            (2, 0, 1),
+            (3, 1, 2),
+            (4, 2, 3),
+            (7, 3, 2),
+            (10, 2, 1),
+            (11, 1, 0),
+        ];
+        crate::assert_with_log!(steps == expected_steps, "steps", expected_steps, steps);
+        for event in &events {
+            let throttling = event.to_level > event.from_level;
+            let moved = if throttling {
+                event.to.overhead < event.from.overhead
+                    && event.to.repair_interval > event.from.repair_interval
+            } else {
+                event.to.overhead > event.from.overhead
+                    && event.to.repair_interval < event.from.repair_interval
+            };
+            crate::assert_with_log!(moved, "rate follows level", true, event);
+            crate::assert_with_log!(
+                event.cause == AdaptationCause::Pressure,
+                "pressure cause",
+                AdaptationCause::Pressure,
+                event.cause
+            );
+        }
+
+        // A sudden spike still moves one level per observation.
+        pressure.set_headroom(0.0);
+        adaptation.observe();
+        crate::assert_with_log!(adaptation.level() == 1, "single step", 1, adaptation.level());
+        crate::test_complete!("pressure_ramp_steps_levels_with_hysteresis");
+    }
+
+    fn saturated(policy: ConflictPolicy) -> (Arc<SystemPressure>, RepairAdaptation) {
+        let config = AdaptiveRepairConfig {
+            max_overhead: 1.5,
+            policy,
+            ..AdaptiveRepairConfig::default()
+        };
+        let (pressure, mut adaptation) = scripted(config);
+        pressure.set_headroom(0.0);
+        for _ in 0..4 {
+            adaptation.observe();
+        }
+        (pressure, adaptation)
+    }
+
+    #[test]
+    fn loss_and_pressure_conflict_resolves_per_policy() {
+        init_test("loss_and_pressure_conflict_resolves_per_policy");
+        let (_protect_pressure, mut protect) = saturated(ConflictPolicy::ProtectDelivery);
+        let (_shed_pressure, mut shed) = saturated(ConflictPolicy::ShedLoad);
+        let ceiling = protect.rate().overhead;
+        crate::assert_with_log!(protect.level() == 4, "saturated", 4, protect.level());
+        crate::assert_with_log!(ceiling == 1.02, "floor overhead", 1.02, ceiling);
+
+        // 20% loss needs 1 / 0.8 + 0.05 = 1.30.
+        protect.record_acks(80, 20);
+        shed.record_acks(80, 20);
+        let event = protect.observe().expect("loss raises repair");
+        crate::assert_with_log!(
+            event.cause == AdaptationCause::Loss,
+            "loss cause",
+            AdaptationCause::Loss,
+            event.cause
+        );
+        let overhead = protect.rate().overhead;
+        crate::assert_with_log!(
+            (overhead - 1.30).abs() < 1e-9,
+            "delivery protected",
+            1.30,
+            overhead
+        );
+        let shed_event = shed.observe();
+        crate::assert_with_log!(shed_event.is_none(), "shed ignores loss", true, shed_event);
+        let shed_overhead = shed.rate().overhead;
+        crate::assert_with_log!(shed_overhead == ceiling, "load shed", ceiling, shed_overhead);
+
+        // Pacing follows pressure under both policies.
+        let max_interval = protect.config().max_repair_interval;
+        for rate in [protect.rate(), shed.rate()] {
+            crate::assert_with_log!(
+                rate.repair_interval == max_interval,
+                "paced",
+                max_interval,
+                rate.repair_interval
+            );
+        }
+        crate::test_complete!("loss_and_pressure_conflict_resolves_per_policy");
+    }
+
+    #[test]
+    fn low_loss_saves_repair_without_pressure_and_bounds_hold() {
+        init_test("low_loss_saves_repair_without_pressure_and_bounds_hold");
+        let config = AdaptiveRepairConfig::default();
+        let (_pressure, mut adaptation) = scripted(config.clone());
+        adaptation.observe();
+        let idle = adaptation.rate().overhead;
+        crate::assert_with_log!(idle == config.max_overhead, "no feedback", 1.25, idle);
+
+        adaptation.record_path_quality(PathQualitySnapshot::new(20, 10, 0));
+        adaptation.observe();
+        let low = adaptation.rate().overhead;
+        let expected = 1.0 / 0.99 + 0.05;
+        crate::assert_with_log!((low - expected).abs() < 1e-9, "low loss", expected, low);
+
+        // Total loss and all-lost ACK reports clamp to the configured maximum.
+        adaptation.record_acks(0, 500);
+        adaptation.observe();
+        assert_within_bounds(&config, adaptation.rate());
+        let total = adaptation.rate().overhead;
+        crate::assert_with_log!(total == config.max_overhead, "clamped", 1.25, total);
+        adaptation.record_acks(0, 0);
+        crate::assert_with_log!(
+            adaptation.loss_permille() == Some(1000),
+            "empty report ignored",
+            Some(1000),
+            adaptation.loss_permille()
+        );
+
+        let bad = [
+            AdaptiveRepairConfig {
+                min_overhead: 0.9,
+                ..AdaptiveRepairConfig::default()
+            },
+            AdaptiveRepairConfig {
+                max_overhead: 1.01,
+                ..AdaptiveRepairConfig::default()
+            },
+            AdaptiveRepairConfig {
+                max_overhead: f64::NAN,
+                ..AdaptiveRepairConfig::default()
+            },
+            AdaptiveRepairConfig {
+                min_repair_interval: Duration::from_millis(2),
+                ..AdaptiveRepairConfig::default()
+            },
+            AdaptiveRepairConfig {
+                levels: 0,
+                ..AdaptiveRepairConfig::default()
+            },
+            AdaptiveRepairConfig {
+                hysteresis_permille: 1000,
+                ..AdaptiveRepairConfig::default()
+            },
+        ];
+        for config in bad {
+            let rejected = RepairAdaptation::new(config.clone(), PressureSignal::callback(|| 1.0));
+            crate::assert_with_log!(rejected.is_err(), "rejected", config, rejected.is_err());
+        }
+        crate::test_complete!("low_loss_saves_repair_without_pressure_and_bounds_hold");
+    }
+
+    #[test]
+    fn pipeline_repair_count_follows_adaptation() {
+        init_test("pipeline_repair_count_follows_adaptation");
+        let headroom = Arc::new(Mutex::new(1.0_f32));
+        let reading = Arc::clone(&headroom);
+        let adaptation = RepairAdaptation::new(
+            AdaptiveRepairConfig::default(),
+            PressureSignal::callback(move || *reading.lock()),
+        )
+        .expect("valid config");
+        let pool = SymbolPool::new(PoolConfig::default());
+        let mut pipeline =
+            EncodingPipeline::new(encoding_config(), pool).with_adaptive_repair(adaptation);
+        let data = vec![3u8; 4096];
+        let object_id = ObjectId::new_for_test(1);
+
+        let count = |pipeline: &mut EncodingPipeline| {
+            pipeline.encode(object_id, &data).for_each(|symbol| {
+                symbol.expect("encode");
+            });
+            pipeline.stats().repair_symbols
+        };
+        // K = 16: 1.25 -> 4 repair symbols, 1.02 -> 1.
+        let relaxed = count(&mut pipeline);
+        crate::assert_with_log!(relaxed == 4, "unthrottled repair", 4, relaxed);
+        *headroom.lock() = 0.0;
+        for _ in 0..4 {
+            count(&mut pipeline);
+        }
+        let throttled = count(&mut pipeline);
+        crate::assert_with_log!(throttled == 1, "throttled repair", 1, throttled);
+        let rate = pipeline.repair_rate();
+        crate::assert_with_log!(rate.overhead == 1.02, "rate exposed", 1.02, rate.overhead);
+
+        // An explicit repair count bypasses adaptation.
+        let explicit = pipeline
+            .encode_with_repair(object_id, &data, 6)
+            .filter(|symbol| symbol.as_ref().is_ok_and(|s| s.kind().is_repair()))
+            .count();
+        crate::assert_with_log!(explicit == 6, "explicit repair", 6, explicit);
+        crate::test_complete!("pipeline_repair_count_follows_adaptation");
+    }
+
+    fn encoding_config() -> EncodingConfig {
+        EncodingConfig {
+            symbol_size: 256,
+            max_block_size: 4096,
+            repair_overhead: 1.05,
+            encoding_parallelism: 1,
+            decoding_parallelism: 1,
+        }
+    }
+
+    /// Encodes `data`, drops every tenth symbol, decodes the rest, and
+    /// reports the receiver's ACK counts.
+    fn lossy_round_trip(
+        pipeline: &mut EncodingPipeline,
+        object_id: ObjectId,
+        data: &[u8],
+    ) -> (bool, u64, u64) {
+        let config = encoding_config();
+        let mut decoder = DecodingPipeline::new(DecodingConfig {
+            symbol_size: config.symbol_size,
+            max_block_size: config.max_block_size,
+            repair_overhead: 1.0,
+            min_overhead: 0,
+            ..DecodingConfig::without_auth()
+        });
+        let k = data.len().div_ceil(usize::from(config.symbol_size)) as u16;
+        let params = ObjectParams::new(object_id, data.len() as u64, config.symbol_size, 1, k);
+        decoder.set_object_params(params).expect("params");
+
+        let (mut delivered, mut lost) = (0, 0);
+        let symbols: Vec<_> = pipeline
+            .encode(object_id, data)
+            .map(|symbol| symbol.expect("encode").into_symbol())
+            .collect();
+        for (index, symbol) in symbols.into_iter().enumerate() {
+            if index % 10 == 9 {
+                lost += 1;
+                continue;
+            }
+            delivered += 1;
+            decoder
+                .feed(AuthenticatedSymbol::new_unauthenticated(symbol))
+                .expect("feed");
+        }
+        let decoded = decoder.into_data().is_ok_and(|decoded| decoded == data);
+        (decoded, delivered, lost)
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct CycleRun {
+        levels: Vec<u8>,
+        decoded: Vec<bool>,
+        events: Vec<(u64, u8, u8, u16)>,
+    }
+
+    fn run_adaptation_cycle(seed: u64) -> CycleRun {
+        let pressure = Arc::new(SystemPressure::new());
+        let adaptation = RepairAdaptation::new(
+            AdaptiveRepairConfig::default(),
+            PressureSignal::System(Arc::clone(&pressure)),
+        )
+        .expect("valid config");
+        let run = Arc::new(Mutex::new(CycleRun::default()));
+
+        let mut runtime = LabRuntime::new(LabConfig::new(seed));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let task_run = Arc::clone(&run);
+        let (task, _handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let pool = SymbolPool::new(PoolConfig::default());
+                let mut pipeline =
+                    EncodingPipeline::new(encoding_config(), pool).with_adaptive_repair(adaptation);
+                let data: Vec<u8> = (0..4096u32).map(|i| (i * 31 % 251) as u8).collect();
+                let ramp = [1.0, 0.7, 0.5, 0.3, 0.1, 0.1, 0.35, 0.55, 0.75, 0.95, 1.0, 1.0];
+                for (round, headroom) in ramp.into_iter().enumerate() {
+                    pressure.set_headroom(headroom);
+                    let object_id = ObjectId::new_for_test(round as u64 + 1);
+                    let (decoded, delivered, lost) =
+                        lossy_round_trip(&mut pipeline, object_id, &data);
+                    let adaptive = pipeline.adaptive_repair_mut().expect("adaptive");
+                    adaptive.record_acks(delivered, lost);
+                    let mut run = task_run.lock();
+                    run.levels.push(adaptive.level());
+                    run.decoded.push(decoded);
+                    drop(run);
+                    crate::runtime::yield_now().await;
+                }
+                let adaptive = pipeline.adaptive_repair_mut().expect("adaptive");
+                task_run.lock().events = adaptive
+                    .take_events()
+                    .iter()
+                    .map(|event| {
+                        let loss = event.loss_permille.unwrap_or(0);
+                        (event.observation, event.from_level, event.to_level, loss)
+                    })
+                    .collect();
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task, 0);
+        runtime.run_until_quiescent();
+        std::mem::take(&mut *run.lock())
+    }
+
+    #[test]
+    fn lab_adaptation_cycle_keeps_decoding() {
+        init_test("lab_adaptation_cycle_keeps_decoding");
+        let run = run_adaptation_cycle(7);
+
+        let expected_levels = vec![0, 1, 2, 3, 4, 4, 3, 2, 1, 0, 0, 0];
+        crate::assert_with_log!(
+            run.levels == expected_levels,
+            "full throttle cycle",
+            expected_levels,
+            run.levels
+        );
+        let all_decoded = run.decoded.iter().all(|decoded| *decoded);
+        crate::assert_with_log!(all_decoded, "every round decodes", true, run.decoded);
+        crate::assert_with_log!(!run.events.is_empty(), "events recorded", true, run.events);
+
+        let replay = run_adaptation_cycle(7);
+        crate::assert_with_log!(replay == run, "deterministic", run, replay);
+        crate::test_complete!("lab_adaptation_cycle_keeps_decoding");
+    }
+}
@@ -57,6 +57,8 @@ pub enum ErrorKind {
     PollQuotaExhausted,
     /// Cost quota exhausted.
     CostQuotaExhausted,
+    /// File descriptor table exhausted (`EMFILE`/`ENFILE`).
+    FdExhausted,
 
     // === Channels ===
     /// Channel is closed/disconnected.
@@ -180,9 +182,10 @@ impl ErrorKind {
     pub const fn category(&self) -> ErrorCategory {
         match self {
             Self::Cancelled | Self::CancelTimeout => ErrorCategory::Cancellation,
-            Self::DeadlineExceeded | Self::PollQuotaExhausted | Self::CostQuotaExhausted => {
-                ErrorCategory::Budget
-            }
+            Self::DeadlineExceeded
+            | Self::PollQuotaExhausted
+            | Self::CostQuotaExhausted
+            | Self::FdExhausted => ErrorCategory::Budget,
             Self::ChannelClosed | Self::ChannelFull | Self::ChannelEmpty => ErrorCategory::Channel,
             Self::ObligationLeak | Self::ObligationAlreadyResolved | Self::RegionFinalized => {
                 ErrorCategory::Obligation
@@ -237,7 +240,8 @@ impl ErrorKind {
             | Self::QuorumNotReached
             | Self::ThresholdTimeout
             | Self::LeaseRenewalFailed
-            | Self::RateLimited => Recoverability::Transient,
+            | Self::RateLimited
+            | Self::FdExhausted => Recoverability::Transient,
 
             // Permanent errors - do not retry
             Self::Cancelled
@@ -304,7 +308,9 @@ impl ErrorKind {
             | Self::QuorumNotReached
             | Self::LeaseRenewalFailed
             | Self::RateLimited => RecoveryAction::RetryWithBackoff(BackoffHint::DEFAULT),
-            Self::NodeUnavailable => RecoveryAction::RetryWithBackoff(BackoffHint::AGGRESSIVE),
+            Self::NodeUnavailable | Self::FdExhausted => {
+                RecoveryAction::RetryWithBackoff(BackoffHint::AGGRESSIVE)
+            }
 
             // Reconnect - connection is likely broken
             Self::ConnectionLost | Self::StreamEnded => RecoveryAction::RetryWithNewConnection,
@@ -851,6 +857,81 @@ impl Error {
     pub fn internal(detail: impl Into<String>) -> Self {
         Self::new(ErrorKind::Internal).with_message(detail)
     }
+
+    /// Creates a file descriptor exhaustion error.
+    #[must_use]
+    pub fn fd_exhausted(operation: impl Into<String>) -> Self {
+        Self::new(ErrorKind::FdExhausted).with_message(format!(
+            "file descriptors exhausted during {}",
+            operation.into()
+        ))
+    }
+
+    /// Recovers the typed error carried by an `io::Error`.
+    ///
+    /// Returns the wrapped [`Error`] when `err` was produced by
+    /// [`map_fd_exhaustion`] (or otherwise boxes an `Error`), and classifies a
+    /// raw `EMFILE`/`ENFILE` as [`ErrorKind::FdExhausted`]. Other I/O errors
+    /// return `None`.
+    #[must_use]
+    pub fn from_io(err: &std::io::Error) -> Option<Self> {
+        if let Some(inner) = err.get_ref().and_then(|inner| inner.downcast_ref::<Self>()) {
+            return Some(inner.clone());
+        }
+        let code = err
+            .raw_os_error()
+            .filter(|code| is_fd_exhaustion_code(*code))?;
+        Some(Self::fd_exhausted("I/O").with_source(std::io::Error::from_raw_os_error(code)))
+    }
+}
+
+/// Returns true if `code` is the platform's per-process or system-wide file
+/// descriptor exhaustion errno.
+fn is_fd_exhaustion_code(code: i32) -> bool {
+    #[cfg(unix)]
+    {
+        code == libc::EMFILE || code == libc::ENFILE
+    }
+    #[cfg(windows)]
+    {
+        // WSAEMFILE: the socket table is full.
+        code == 10_024
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = code;
+        false
+    }
+}
+
+/// Returns true if `err` reports file descriptor exhaustion, either as a raw
+/// `EMFILE`/`ENFILE` or as an already-mapped [`ErrorKind::FdExhausted`].
+#[must_use]
+pub fn is_fd_exhaustion(err: &std::io::Error) -> bool {
+    if err.raw_os_error().is_some_and(is_fd_exhaustion_code) {
+        return true;
+    }
+    err.get_ref()
+        .and_then(|inner| inner.downcast_ref::<Error>())
+        .is_some_and(|inner| inner.kind() == ErrorKind::FdExhausted)
+}
+
+/// Maps a raw `EMFILE`/`ENFILE` into an `io::Error` carrying a typed
+/// [`ErrorKind::FdExhausted`] error.
+///
+/// The result keeps the `io::Result` signature of the call site while letting
+/// retry and load-shedding layers recover the classification with
+/// [`Error::from_io`]: the typed error is transient and recommends
+/// [`BackoffHint::AGGRESSIVE`]. Any other error is returned unchanged.
+#[must_use]
+pub fn map_fd_exhaustion(err: std::io::Error) -> std::io::Error {
+    match err.raw_os_error() {
+        Some(code) if is_fd_exhaustion_code(code) => std::io::Error::new(
+            std::io::ErrorKind::QuotaExceeded,
+            Error::fd_exhausted("I/O").with_source(err),
+        ),
+        _ => err,
+    }
 }
 
 impl fmt::Display for Error {
@@ -1150,6 +1231,7 @@ mod tests {
             (ErrorKind::DeadlineExceeded, Budget),
             (ErrorKind::PollQuotaExhausted, Budget),
             (ErrorKind::CostQuotaExhausted, Budget),
+            (ErrorKind::FdExhausted, Budget),
             (ErrorKind::ChannelClosed, Channel),
             (ErrorKind::ChannelFull, Channel),
             (ErrorKind::ChannelEmpty, Channel),
@@ -1203,6 +1285,7 @@ mod tests {
             ErrorKind::QuorumNotReached,
             ErrorKind::ThresholdTimeout,
             ErrorKind::LeaseRenewalFailed,
+            ErrorKind::FdExhausted,
         ] {
             assert_eq!(kind.recoverability(), Recoverability::Transient, "{kind:?}");
             assert!(kind.is_retryable(), "{kind:?} should be retryable");
@@ -1329,6 +1412,38 @@ mod tests {
         assert!(err.to_string().contains("lease-42"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn fd_exhaustion_maps_to_typed_transient_error() {
+        for code in [libc::EMFILE, libc::ENFILE] {
+            let raw = std::io::Error::from_raw_os_error(code);
+            assert!(is_fd_exhaustion(&raw));
+
+            let mapped = map_fd_exhaustion(raw);
+            assert_eq!(mapped.kind(), std::io::ErrorKind::QuotaExceeded);
+            assert!(is_fd_exhaustion(&mapped));
+
+            let typed = Error::from_io(&mapped).expect("typed error");
+            assert_eq!(typed.kind(), ErrorKind::FdExhausted);
+            assert_eq!(typed.category(), ErrorCategory::Budget);
+            assert_eq!(typed.recoverability(), Recoverability::Transient);
+            assert_eq!(
+                typed.recovery_action(),
+                RecoveryAction::RetryWithBackoff(BackoffHint::AGGRESSIVE)
+            );
+            assert!(std::error::Error::source(&typed).is_some());
+
+            let direct = Error::from_io(&std::io::Error::from_raw_os_error(code)).expect("raw");
+            assert_eq!(direct.kind(), ErrorKind::FdExhausted);
+        }
+
+        let other = std::io::Error::from_raw_os_error(libc::ECONNRESET);
+        assert!(!is_fd_exhaustion(&other));
+        assert!(Error::from_io(&other).is_none());
+        let passthrough = map_fd_exhaustion(other);
+        assert_eq!(passthrough.raw_os_error(), Some(libc::ECONNRESET));
+    }
+
     #[test]
     fn error_quorum_not_reached() {
         let err = Error::quorum_not_reached(2, 3);
@@ -27,6 +27,22 @@
 //! ├── detail: EvidenceDetail (enum over subsystem-specific constraints)
 //! └── verdict: Verdict (one-word outcome: Restart, Stop, Escalate, Accept, Reject, Propagate, …)
 //! ```
+//!
+//! Persisted runtime decision evidence ([`franken_evidence::EvidenceLedger`]
+//! NDJSON) is searched with [`Query`]; see the [`query`] module. Its schema
+//! versions, migrations and dual-write support live in [`schema`].
+
+pub mod query;
+pub mod schema;
+
+pub use query::{
+    ComponentFilter, EvidenceIndex, EvidenceIndexBuilder, EvidenceSource, FieldPredicate,
+    MalformedLine, PredicateOp, Query, QueryOutput, QueryScan, QuerySummary,
+};
+pub use schema::{
+    ContractFields, CorpusFailure, CorpusReport, DualWriteSink, EvidenceEntry, MigrationFn,
+    MigrationRegistry, SchemaError, SchemaVersion, validate_corpus,
+};
 
 use std::fmt;
 use std::time::Duration;
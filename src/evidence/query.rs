@@ -0,0 +1,1114 @@
+//! Query layer over persisted [`EvidenceLedger`] NDJSON evidence.
+//!
+//! [`JsonlSink`](crate::evidence_sink::JsonlSink) persists one
+//! [`EvidenceLedger`] per line. A [`Query`] scans one or more such files (or
+//! any buffered reader, e.g. a streaming export) and yields the matching
+//! entries together with a [`QuerySummary`] describing the scan:
+//!
+//! ```no_run
+//! use asupersync::evidence::Query;
+//!
+//! let output = Query::new()
+//!     .component("transport.path_select")
+//!     .field_eq("fallback_active", true)
+//!     .time_range(1_700_000_000_000, 1_700_086_400_000)
+//!     .limit(100)
+//!     .execute(["evidence.jsonl"])
+//!     .unwrap();
+//! assert!(output.entries.iter().all(|entry| entry.fallback_active));
+//! let malformed: Vec<String> = output
+//!     .summary
+//!     .malformed
+//!     .iter()
+//!     .map(ToString::to_string)
+//!     .collect();
+//! ```
+//!
+//! # Semantics
+//!
+//! - `time_range(start, end)` is inclusive on **both** ends.
+//! - Field predicates address entries by their long field names
+//!   (`fallback_active`, `calibration_score`, …) or the short serde names
+//!   (`fb`, `cal`, …). `expected_loss.<action>` and `feature.<name>` reach into
+//!   the per-action loss map and the top features. A predicate on a field the
+//!   entry does not have never matches.
+//! - Entries of every readable schema version (see [`schema`](super::schema))
+//!   are matched on their ledger fields, so files written before, during and
+//!   after a schema migration can be queried together.
+//! - Lines that fail to parse or validate are never dropped silently: each
+//!   is reported in [`QuerySummary::malformed`] with its line number and byte
+//!   offset. Blank lines and schema header lines are not entries.
+//!
+//! # Sidecar index
+//!
+//! An [`EvidenceIndex`] stored next to a file (`<file>.idx`) records the
+//! file's timestamp range and a bloom filter over its component names. When a
+//! current index proves a file cannot match, the scan skips it and lists it
+//! in [`QuerySummary::files_skipped`]. An index is current only while the
+//! file length equals the length it was built for; stale or unreadable
+//! indexes are ignored (and listed in [`QuerySummary::stale_indexes`]), so
+//! skipping never changes query results.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use franken_evidence::EvidenceLedger;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Sidecar index format version.
+const INDEX_VERSION: u32 = 1;
+
+/// Extension appended to an evidence file name to form its sidecar index.
+const INDEX_EXTENSION: &str = "idx";
+
+/// Bloom filter size in 64-bit words (1024 bits).
+const BLOOM_WORDS: usize = 16;
+
+/// Number of bloom probes per component name.
+const BLOOM_HASHES: u64 = 4;
+
+// ---------------------------------------------------------------------------
+// Predicates
+// ---------------------------------------------------------------------------
+
+/// Comparison applied by a [`FieldPredicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PredicateOp {
+    /// Field equals the value.
+    Eq,
+    /// Field is present and differs from the value.
+    Ne,
+    /// Numeric field is greater than or equal to the value.
+    Ge,
+    /// Numeric field is less than or equal to the value.
+    Le,
+}
+
+/// A predicate over one field of an evidence entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldPredicate {
+    /// Field name (long or short serde name).
+    pub field: String,
+    /// Comparison to apply.
+    pub op: PredicateOp,
+    /// Value compared against.
+    pub value: Value,
+}
+
+impl FieldPredicate {
+    /// Returns `true` if `entry` satisfies this predicate.
+    #[must_use]
+    pub fn matches(&self, entry: &EvidenceLedger) -> bool {
+        let Some(actual) = entry_field(entry, &self.field) else {
+            return false;
+        };
+        match self.op {
+            PredicateOp::Eq => values_equal(&actual, &self.value),
+            PredicateOp::Ne => !values_equal(&actual, &self.value),
+            PredicateOp::Ge => {
+                matches!((actual.as_f64(), self.value.as_f64()), (Some(a), Some(b)) if a >= b)
+            }
+            PredicateOp::Le => {
+                matches!((actual.as_f64(), self.value.as_f64()), (Some(a), Some(b)) if a <= b)
+            }
+        }
+    }
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b).is_eq(),
+        _ => actual == expected,
+    }
+}
+
+/// Resolves a field of `entry` by long or short name.
+fn entry_field(entry: &EvidenceLedger, field: &str) -> Option<Value> {
+    if let Some(action) = field
+        .strip_prefix("expected_loss.")
+        .or_else(|| field.strip_prefix("el."))
+    {
+        return entry
+            .expected_loss_by_action
+            .get(action)
+            .map(|loss| Value::from(*loss));
+    }
+    if let Some(name) = field
+        .strip_prefix("feature.")
+        .or_else(|| field.strip_prefix("tf."))
+    {
+        return entry
+            .top_features
+            .iter()
+            .find(|(feature, _)| feature == name)
+            .map(|(_, weight)| Value::from(*weight));
+    }
+    let value = match field {
+        "ts_unix_ms" | "ts" => Value::from(entry.ts_unix_ms),
+        "component" | "c" => Value::from(entry.component.as_str()),
+        "action" | "a" => Value::from(entry.action.as_str()),
+        "chosen_expected_loss" | "cel" => Value::from(entry.chosen_expected_loss),
+        "calibration_score" | "cal" => Value::from(entry.calibration_score),
+        "fallback_active" | "fb" => Value::from(entry.fallback_active),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// ---------------------------------------------------------------------------
+// Query
+// ---------------------------------------------------------------------------
+
+/// A filter over persisted evidence entries.
+///
+/// All configured conditions must hold for an entry to match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    component: Option<String>,
+    action: Option<String>,
+    predicates: Vec<FieldPredicate>,
+    time_range: Option<(u64, u64)>,
+    limit: Option<usize>,
+    use_index: bool,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Query {
+    /// Creates a query that matches every entry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            component: None,
+            action: None,
+            predicates: Vec::new(),
+            time_range: None,
+            limit: None,
+            use_index: true,
+        }
+    }
+
+    /// Only match entries produced by `component`.
+    #[must_use]
+    pub fn component(mut self, component: impl Into<String>) -> Self {
+        self.component = Some(component.into());
+        self
+    }
+
+    /// Only match entries whose chosen action is `action`.
+    #[must_use]
+    pub fn action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Only match entries whose `field` equals `value`.
+    #[must_use]
+    pub fn field_eq(self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.predicate(field, PredicateOp::Eq, value)
+    }
+
+    /// Only match entries that have `field` with a value other than `value`.
+    #[must_use]
+    pub fn field_ne(self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.predicate(field, PredicateOp::Ne, value)
+    }
+
+    /// Only match entries whose numeric `field` is at least `value`.
+    #[must_use]
+    pub fn field_ge(self, field: impl Into<String>, value: f64) -> Self {
+        self.predicate(field, PredicateOp::Ge, value)
+    }
+
+    /// Only match entries whose numeric `field` is at most `value`.
+    #[must_use]
+    pub fn field_le(self, field: impl Into<String>, value: f64) -> Self {
+        self.predicate(field, PredicateOp::Le, value)
+    }
+
+    /// Adds an arbitrary field predicate.
+    #[must_use]
+    pub fn predicate(
+        mut self,
+        field: impl Into<String>,
+        op: PredicateOp,
+        value: impl Into<Value>,
+    ) -> Self {
+        self.predicates.push(FieldPredicate {
+            field: field.into(),
+            op,
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Only match entries with `start_ms <= ts_unix_ms <= end_ms`.
+    #[must_use]
+    pub fn time_range(mut self, start_ms: u64, end_ms: u64) -> Self {
+        self.time_range = Some((start_ms, end_ms));
+        self
+    }
+
+    /// Stop after `limit` matching entries.
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Enables or disables sidecar index skipping (enabled by default).
+    #[must_use]
+    pub fn use_index(mut self, use_index: bool) -> Self {
+        self.use_index = use_index;
+        self
+    }
+
+    /// Returns `true` if `entry` satisfies every condition of this query.
+    #[must_use]
+    pub fn matches(&self, entry: &EvidenceLedger) -> bool {
+        self.component
+            .as_ref()
+            .is_none_or(|component| entry.component == *component)
+            && self
+                .action
+                .as_ref()
+                .is_none_or(|action| entry.action == *action)
+            && self
+                .time_range
+                .is_none_or(|(start, end)| (start..=end).contains(&entry.ts_unix_ms))
+            && self
+                .predicates
+                .iter()
+                .all(|predicate| predicate.matches(entry))
+    }
+
+    /// Starts a lazy scan over `sources`.
+    pub fn scan<I>(&self, sources: I) -> QueryScan<'_>
+    where
+        I: IntoIterator,
+        I::Item: Into<EvidenceSource>,
+    {
+        QueryScan {
+            query: self,
+            pending: sources.into_iter().map(Into::into).collect(),
+            current: None,
+            summary: QuerySummary::default(),
+            done: false,
+        }
+    }
+
+    /// Scans `sources` to completion, collecting matches and the summary.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first I/O error raised while opening or reading a source.
+    pub fn execute<I>(&self, sources: I) -> io::Result<QueryOutput>
+    where
+        I: IntoIterator,
+        I::Item: Into<EvidenceSource>,
+    {
+        let mut scan = self.scan(sources);
+        let mut entries = Vec::new();
+        for entry in &mut scan {
+            entries.push(entry?);
+        }
+        Ok(QueryOutput {
+            entries,
+            summary: scan.into_summary(),
+        })
+    }
+
+    fn limit_reached(&self, matched: usize) -> bool {
+        self.limit.is_some_and(|limit| matched >= limit)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sources
+// ---------------------------------------------------------------------------
+
+/// A stream of NDJSON evidence to scan.
+pub struct EvidenceSource {
+    label: String,
+    kind: SourceKind,
+}
+
+enum SourceKind {
+    File(PathBuf),
+    Reader(Box<dyn BufRead + Send>),
+}
+
+impl EvidenceSource {
+    /// A persisted evidence file; its sidecar index is consulted if present.
+    #[must_use]
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            label: path.display().to_string(),
+            kind: SourceKind::File(path),
+        }
+    }
+
+    /// An arbitrary reader, e.g. a streaming export. Never index-skipped.
+    #[must_use]
+    pub fn reader(label: impl Into<String>, reader: impl BufRead + Send + 'static) -> Self {
+        Self {
+            label: label.into(),
+            kind: SourceKind::Reader(Box::new(reader)),
+        }
+    }
+
+    /// Label used for this source in the [`QuerySummary`].
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+impl fmt::Debug for EvidenceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            SourceKind::File(_) => "file",
+            SourceKind::Reader(_) => "reader",
+        };
+        f.debug_struct("EvidenceSource")
+            .field("label", &self.label)
+            .field("kind", &kind)
+            .finish()
+    }
+}
+
+impl From<PathBuf> for EvidenceSource {
+    fn from(path: PathBuf) -> Self {
+        Self::file(path)
+    }
+}
+
+impl From<&Path> for EvidenceSource {
+    fn from(path: &Path) -> Self {
+        Self::file(path)
+    }
+}
+
+impl From<&PathBuf> for EvidenceSource {
+    fn from(path: &PathBuf) -> Self {
+        Self::file(path.clone())
+    }
+}
+
+impl From<&str> for EvidenceSource {
+    fn from(path: &str) -> Self {
+        Self::file(path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scan
+// ---------------------------------------------------------------------------
+
+/// A line that could not be read as an evidence entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MalformedLine {
+    /// Label of the source containing the line.
+    pub source: String,
+    /// 1-based line number.
+    pub line: u64,
+    /// Byte offset of the start of the line.
+    pub byte_offset: u64,
+    /// Parse or validation error.
+    pub error: String,
+}
+
+impl fmt::Display for MalformedLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} (byte {}): {}",
+            self.source, self.line, self.byte_offset, self.error
+        )
+    }
+}
+
+/// Execution summary of a query scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuerySummary {
+    /// Files or readers that were read.
+    pub files_scanned: usize,
+    /// Files skipped because their sidecar index proved they cannot match.
+    pub files_skipped: Vec<String>,
+    /// Files whose sidecar index was stale or unreadable and was ignored.
+    pub stale_indexes: Vec<String>,
+    /// Entries that matched the query.
+    pub entries_matched: usize,
+    /// Well-formed entries that did not match the query.
+    pub entries_skipped: usize,
+    /// Lines that could not be parsed or validated.
+    pub malformed: Vec<MalformedLine>,
+    /// `true` if the scan stopped because the query limit was reached.
+    pub limit_reached: bool,
+}
+
+/// Matches and summary of a completed query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryOutput {
+    /// Matching entries, in source order.
+    pub entries: Vec<EvidenceLedger>,
+    /// Execution summary.
+    pub summary: QuerySummary,
+}
+
+/// Lazy scan produced by [`Query::scan`].
+///
+/// Yields matching entries in source order. An I/O error ends the current
+/// source; the scan then continues with the next one.
+pub struct QueryScan<'q> {
+    query: &'q Query,
+    pending: VecDeque<EvidenceSource>,
+    current: Option<OpenSource>,
+    summary: QuerySummary,
+    done: bool,
+}
+
+struct OpenSource {
+    label: String,
+    lines: LineReader,
+}
+
+impl QueryScan<'_> {
+    /// Summary of the scan so far.
+    #[must_use]
+    pub fn summary(&self) -> &QuerySummary {
+        &self.summary
+    }
+
+    /// Consumes the scan, returning its summary.
+    #[must_use]
+    pub fn into_summary(self) -> QuerySummary {
+        self.summary
+    }
+
+    /// Opens the next source, or returns `None` if the index skips it.
+    fn open(&mut self, source: EvidenceSource) -> io::Result<Option<OpenSource>> {
+        let reader = match source.kind {
+            SourceKind::Reader(reader) => reader,
+            SourceKind::File(path) => {
+                if self.query.use_index {
+                    match EvidenceIndex::load_current(&path) {
+                        Ok(Some(index)) if !index.may_match(self.query) => {
+                            self.summary.files_skipped.push(source.label);
+                            return Ok(None);
+                        }
+                        Ok(_) => {}
+                        Err(_) => self.summary.stale_indexes.push(source.label.clone()),
+                    }
+                }
+                let file = std::fs::File::open(&path)?;
+                Box::new(BufReader::new(file))
+            }
+        };
+        self.summary.files_scanned += 1;
+        Ok(Some(OpenSource {
+            label: source.label,
+            lines: LineReader::new(reader),
+        }))
+    }
+}
+
+impl Iterator for QueryScan<'_> {
+    type Item = io::Result<EvidenceLedger>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+            if self.query.limit_reached(self.summary.entries_matched) {
+                self.summary.limit_reached = true;
+                self.done = true;
+                return None;
+            }
+            let Some(open) = self.current.as_mut() else {
+                let Some(source) = self.pending.pop_front() else {
+                    self.done = true;
+                    return None;
+                };
+                match self.open(source) {
+                    Ok(open) => self.current = open,
+                    Err(error) => return Some(Err(error)),
+                }
+                continue;
+            };
+
+            let line = match open.lines.next_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    self.current = None;
+                    continue;
+                }
+                Err(error) => {
+                    self.current = None;
+                    return Some(Err(error));
+                }
+            };
+            match line.parse() {
+                ParsedLine::Blank => {}
+                ParsedLine::Malformed(error) => self.summary.malformed.push(MalformedLine {
+                    source: open.label.clone(),
+                    line: line.number,
+                    byte_offset: line.offset,
+                    error,
+                }),
+                ParsedLine::Entry(entry) => {
+                    if self.query.matches(&entry) {
+                        self.summary.entries_matched += 1;
+                        return Some(Ok(*entry));
+                    }
+                    self.summary.entries_skipped += 1;
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for QueryScan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryScan")
+            .field("query", self.query)
+            .field("pending", &self.pending.len())
+            .field("summary", &self.summary)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Line reader that tracks line numbers and byte offsets.
+struct LineReader {
+    reader: Box<dyn BufRead + Send>,
+    buf: Vec<u8>,
+    line: u64,
+    offset: u64,
+}
+
+struct RawLine<'a> {
+    number: u64,
+    offset: u64,
+    bytes: &'a [u8],
+}
+
+enum ParsedLine {
+    Blank,
+    Malformed(String),
+    Entry(Box<EvidenceLedger>),
+}
+
+impl LineReader {
+    fn new(reader: Box<dyn BufRead + Send>) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            line: 0,
+            offset: 0,
+        }
+    }
+
+    fn next_line(&mut self) -> io::Result<Option<RawLine<'_>>> {
+        self.buf.clear();
+        let read = self.reader.read_until(b'\n', &mut self.buf)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let offset = self.offset;
+        self.offset += read as u64;
+        self.line += 1;
+        Ok(Some(RawLine {
+            number: self.line,
+            offset,
+            bytes: &self.buf,
+        }))
+    }
+
+    /// Bytes consumed so far.
+    fn bytes_read(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl RawLine<'_> {
+    fn parse(&self) -> ParsedLine {
+        let text = match std::str::from_utf8(self.bytes) {
+            Ok(text) => text.trim(),
+            Err(error) => return ParsedLine::Malformed(error.to_string()),
+        };
+        // Schema headers written by the exporter are not entries.
+        if text.is_empty() || text.contains("\"_schema\"") {
+            return ParsedLine::Blank;
+        }
+        // Older and newer-minor schema versions are upgraded transparently.
+        match super::schema::builtin_registry().decode_line(text) {
+            Ok(entry) => ParsedLine::Entry(Box::new(entry.ledger)),
+            Err(error) => ParsedLine::Malformed(error.to_string()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sidecar index
+// ---------------------------------------------------------------------------
+
+/// Bloom filter over the component names of an evidence file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentFilter {
+    bits: Vec<u64>,
+}
+
+impl Default for ComponentFilter {
+    fn default() -> Self {
+        Self {
+            bits: vec![0; BLOOM_WORDS],
+        }
+    }
+}
+
+impl ComponentFilter {
+    /// Records `component` in the filter.
+    pub fn insert(&mut self, component: &str) {
+        for bit in bloom_bits(component) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `false` only if `component` was definitely never inserted.
+    #[must_use]
+    pub fn may_contain(&self, component: &str) -> bool {
+        if self.bits.len() != BLOOM_WORDS {
+            return true;
+        }
+        bloom_bits(component).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
+
+fn bloom_bits(component: &str) -> impl Iterator<Item = usize> {
+    let h1 = fnv1a64(component.as_bytes());
+    let h2 = fnv1a64(&h1.to_le_bytes()) | 1;
+    let total = (BLOOM_WORDS * 64) as u64;
+    (0..BLOOM_HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % total) as usize)
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Per-file summary used to skip evidence files that cannot match a query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceIndex {
+    /// Index format version.
+    pub version: u32,
+    /// Length of the evidence file this index describes.
+    pub indexed_bytes: u64,
+    /// Number of well-formed entries.
+    pub entries: u64,
+    /// Earliest entry timestamp, if any entries exist.
+    pub min_ts_unix_ms: Option<u64>,
+    /// Latest entry timestamp, if any entries exist.
+    pub max_ts_unix_ms: Option<u64>,
+    /// Bloom filter over component names.
+    pub components: ComponentFilter,
+}
+
+impl EvidenceIndex {
+    /// Sidecar index path for the evidence file at `path`.
+    #[must_use]
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(INDEX_EXTENSION);
+        path.with_file_name(name)
+    }
+
+    /// Builds an index by scanning the evidence file at `path`.
+    ///
+    /// Malformed lines are not indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns any I/O error raised while reading the file.
+    pub fn build(path: &Path) -> io::Result<Self> {
+        let (builder, indexed_bytes) = index_file(path)?;
+        Ok(builder.finish(indexed_bytes))
+    }
+
+    /// Rebuilds the index for `path` and writes it to the sidecar file.
+    ///
+    /// # Errors
+    ///
+    /// Returns any I/O error raised while reading or writing.
+    pub fn reindex(path: &Path) -> io::Result<Self> {
+        let index = Self::build(path)?;
+        index.write_sidecar(path)?;
+        Ok(index)
+    }
+
+    /// Writes this index to the sidecar file of `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any I/O error raised while writing.
+    pub fn write_sidecar(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec(self).map_err(io::Error::other)?;
+        std::fs::write(Self::sidecar_path(path), json)
+    }
+
+    /// Loads the sidecar index of `path`, if one exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the sidecar cannot be read, fails to parse,
+    /// or has an unsupported version.
+    pub fn load_sidecar(path: &Path) -> io::Result<Option<Self>> {
+        let bytes = match std::fs::read(Self::sidecar_path(path)) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let index: Self = serde_json::from_slice(&bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if index.version != INDEX_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported evidence index version {}", index.version),
+            ));
+        }
+        Ok(Some(index))
+    }
+
+    /// Loads the sidecar index of `path` if it describes the file as it is now.
+    ///
+    /// Returns `Ok(None)` when there is no sidecar.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sidecar is unreadable or stale.
+    fn load_current(path: &Path) -> io::Result<Option<Self>> {
+        let Some(index) = Self::load_sidecar(path)? else {
+            return Ok(None);
+        };
+        let len = std::fs::metadata(path)?.len();
+        if len != index.indexed_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "evidence index is stale",
+            ));
+        }
+        Ok(Some(index))
+    }
+
+    /// Returns `false` only if no entry of the indexed file can match `query`.
+    #[must_use]
+    pub fn may_match(&self, query: &Query) -> bool {
+        if self.entries == 0 {
+            return false;
+        }
+        if let (Some((start, end)), Some(min), Some(max)) =
+            (query.time_range, self.min_ts_unix_ms, self.max_ts_unix_ms)
+            && (max < start || min > end)
+        {
+            return false;
+        }
+        query
+            .component
+            .as_ref()
+            .is_none_or(|component| self.components.may_contain(component))
+    }
+}
+
+/// Incrementally builds an [`EvidenceIndex`] as entries are written.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceIndexBuilder {
+    entries: u64,
+    min_ts_unix_ms: Option<u64>,
+    max_ts_unix_ms: Option<u64>,
+    components: ComponentFilter,
+}
+
+impl EvidenceIndexBuilder {
+    /// Creates an empty builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder seeded with the entries already in `path`.
+    ///
+    /// A missing file yields an empty builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns any other I/O error raised while reading the file.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        match index_file(path) {
+            Ok((builder, _)) => Ok(builder),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Records `entry`.
+    pub fn observe(&mut self, entry: &EvidenceLedger) {
+        self.entries += 1;
+        let ts = entry.ts_unix_ms;
+        self.min_ts_unix_ms = Some(self.min_ts_unix_ms.map_or(ts, |min| min.min(ts)));
+        self.max_ts_unix_ms = Some(self.max_ts_unix_ms.map_or(ts, |max| max.max(ts)));
+        self.components.insert(&entry.component);
+    }
+
+    /// Returns an index for a file of `indexed_bytes` bytes.
+    #[must_use]
+    pub fn finish(&self, indexed_bytes: u64) -> EvidenceIndex {
+        EvidenceIndex {
+            version: INDEX_VERSION,
+            indexed_bytes,
+            entries: self.entries,
+            min_ts_unix_ms: self.min_ts_unix_ms,
+            max_ts_unix_ms: self.max_ts_unix_ms,
+            components: self.components.clone(),
+        }
+    }
+}
+
+/// Indexes every well-formed entry of `path`, returning the bytes read.
+fn index_file(path: &Path) -> io::Result<(EvidenceIndexBuilder, u64)> {
+    let file = std::fs::File::open(path)?;
+    let mut lines = LineReader::new(Box::new(BufReader::new(file)));
+    let mut builder = EvidenceIndexBuilder::new();
+    while let Some(line) = lines.next_line()? {
+        if let ParsedLine::Entry(entry) = line.parse() {
+            builder.observe(&entry);
+        }
+    }
+    Ok((builder, lines.bytes_read()))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use franken_evidence::EvidenceLedgerBuilder;
+    use franken_evidence::export::JsonlExporter;
+
+    fn entry(ts: u64, component: &str, action: &str, fallback: bool) -> EvidenceLedger {
+        EvidenceLedgerBuilder::new()
+            .ts_unix_ms(ts)
+            .component(component)
+            .action(action)
+            .posterior(vec![0.6, 0.4])
+            .expected_loss(action, 0.2)
+            .chosen_expected_loss(0.2)
+            .calibration_score(if fallback { 0.3 } else { 0.9 })
+            .fallback_active(fallback)
+            .top_feature("rtt_ms", 12.0)
+            .build()
+            .unwrap()
+    }
+
+    fn write_file(path: &Path, entries: &[EvidenceLedger]) {
+        let mut exporter = JsonlExporter::open(path.to_path_buf()).unwrap();
+        for entry in entries {
+            exporter.append(entry).unwrap();
+        }
+        exporter.flush().unwrap();
+    }
+
+    fn line(entry: &EvidenceLedger) -> String {
+        serde_json::to_string(entry).unwrap()
+    }
+
+    fn sample() -> Vec<EvidenceLedger> {
+        vec![
+            entry(100, "transport.path_select", "direct", false),
+            entry(200, "transport.path_select", "relay", true),
+            entry(300, "scheduler", "preempt", true),
+            entry(400, "transport.path_select", "relay", true),
+        ]
+    }
+
+    #[test]
+    fn predicate_combinations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence.jsonl");
+        write_file(&path, &sample());
+
+        let fallback = Query::new()
+            .component("transport.path_select")
+            .field_eq("fallback_active", true)
+            .execute([&path])
+            .unwrap();
+        let ts: Vec<_> = fallback.entries.iter().map(|e| e.ts_unix_ms).collect();
+        assert_eq!(ts, vec![200, 400]);
+        assert_eq!(fallback.summary.entries_matched, 2);
+        assert_eq!(fallback.summary.entries_skipped, 2);
+        assert_eq!(fallback.summary.files_scanned, 1);
+
+        let not_relay = Query::new()
+            .field_ne("a", "relay")
+            .field_le("calibration_score", 0.5)
+            .execute([&path])
+            .unwrap();
+        let ts: Vec<_> = not_relay.entries.iter().map(|e| e.ts_unix_ms).collect();
+        assert_eq!(ts, vec![300]);
+
+        let nested = Query::new()
+            .action("relay")
+            .field_eq("expected_loss.relay", 0.2)
+            .field_ge("feature.rtt_ms", 12.0)
+            .execute([&path])
+            .unwrap();
+        assert_eq!(nested.entries.len(), 2);
+
+        let unknown = Query::new()
+            .field_ne("no_such_field", 1)
+            .execute([&path])
+            .unwrap();
+        assert!(unknown.entries.is_empty(), "missing fields never match");
+    }
+
+    #[test]
+    fn time_range_is_inclusive_on_both_ends() {
+        let entries = sample();
+        let query = Query::new().time_range(200, 300);
+        assert!(!query.matches(&entries[0]));
+        assert!(query.matches(&entries[1]));
+        assert!(query.matches(&entries[2]));
+        assert!(!query.matches(&entries[3]));
+
+        let point = Query::new().time_range(400, 400);
+        assert!(point.matches(&entries[3]));
+        let empty = Query::new().time_range(301, 399);
+        assert!(entries.iter().all(|entry| !empty.matches(entry)));
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_offsets() {
+        let good = line(&entry(100, "scheduler", "preempt", false));
+        let invalid = line(&entry(200, "scheduler", "preempt", false)).replace("0.6", "0.9");
+        let input = format!(
+            "{{\"_schema\":\"EvidenceLedger\",\"_version\":\"1.0.0\"}}\n{good}\nnot json\n\n{invalid}\n{good}\n{{\"ts\":3"
+        );
+        let header_len = input.find('\n').unwrap() as u64 + 1;
+        let good_len = good.len() as u64 + 1;
+
+        let output = Query::new()
+            .execute([EvidenceSource::reader(
+                "stream",
+                io::Cursor::new(input.into_bytes()),
+            )])
+            .unwrap();
+
+        assert_eq!(output.entries.len(), 2);
+        let malformed = &output.summary.malformed;
+        assert_eq!(malformed.len(), 3, "{malformed:?}");
+        assert_eq!(malformed[0].source, "stream");
+        assert_eq!(malformed[0].line, 3);
+        assert_eq!(malformed[0].byte_offset, header_len + good_len);
+        assert_eq!(malformed[1].line, 5, "posterior that fails validation");
+        assert_eq!(
+            malformed[1].byte_offset,
+            header_len + good_len + "not json\n\n".len() as u64
+        );
+        assert!(malformed[1].error.contains("invalid evidence ledger"));
+        assert_eq!(malformed[2].line, 7, "truncated trailing line");
+    }
+
+    #[test]
+    fn index_skips_files_that_cannot_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let early = dir.path().join("early.jsonl");
+        let late = dir.path().join("late.jsonl");
+        let other = dir.path().join("other.jsonl");
+        write_file(
+            &early,
+            &[entry(100, "transport.path_select", "relay", true)],
+        );
+        write_file(&late, &[entry(900, "transport.path_select", "relay", true)]);
+        write_file(&other, &[entry(500, "scheduler", "preempt", true)]);
+        for path in [&early, &late, &other] {
+            EvidenceIndex::reindex(path).unwrap();
+        }
+
+        let query = Query::new()
+            .component("transport.path_select")
+            .time_range(800, 1_000);
+        let output = query.execute([&early, &late, &other]).unwrap();
+        assert_eq!(output.entries.len(), 1);
+        assert_eq!(output.summary.files_scanned, 1);
+        assert_eq!(
+            output.summary.files_skipped,
+            vec![early.display().to_string(), other.display().to_string()]
+        );
+
+        let unindexed = query.clone().use_index(false);
+        let output = unindexed.execute([&early, &late, &other]).unwrap();
+        assert_eq!(output.summary.files_scanned, 3);
+        assert!(output.summary.files_skipped.is_empty());
+
+        // Appending after indexing makes the index stale: the file is scanned.
+        write_file(
+            &early,
+            &[entry(850, "transport.path_select", "relay", true)],
+        );
+        let output = query.execute([&early, &late, &other]).unwrap();
+        assert_eq!(output.entries.len(), 2);
+        assert_eq!(
+            output.summary.stale_indexes,
+            vec![early.display().to_string()]
+        );
+        assert_eq!(
+            output.summary.files_skipped,
+            vec![other.display().to_string()]
+        );
+    }
+
+    #[test]
+    fn limit_stops_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.jsonl");
+        let second = dir.path().join("second.jsonl");
+        write_file(&first, &sample());
+        write_file(&second, &sample());
+
+        let output = Query::new()
+            .field_eq("fb", true)
+            .limit(2)
+            .execute([&first, &second])
+            .unwrap();
+        assert_eq!(output.entries.len(), 2);
+        assert!(output.summary.limit_reached);
+        assert_eq!(output.summary.files_scanned, 1);
+    }
+
+    #[test]
+    fn component_filter_has_no_false_negatives() {
+        let mut filter = ComponentFilter::default();
+        let names: Vec<_> = (0..64).map(|i| format!("component.{i}")).collect();
+        for name in &names {
+            filter.insert(name);
+        }
+        assert!(names.iter().all(|name| filter.may_contain(name)));
+        assert!(!ComponentFilter::default().may_contain("scheduler"));
+    }
+}
@@ -0,0 +1,1045 @@
+//! Versioned evidence schema: migrations, dual-write and corpus validation.
+//!
+//! Persisted evidence evolves without a flag day:
+//!
+//! - **1.0** is the bare [`EvidenceLedger`] line written by
+//!   [`JsonlSink`](crate::evidence_sink::JsonlSink). It carries no version
+//!   tag; an untagged line is always 1.0.
+//! - **2.0** tags every line with `"v"` and adds the decision-contract fields
+//!   under `"dc"` (contract name, decision id, trace id):
+//!
+//! ```text
+//! {"v":"2.0","ts":…,"c":"scheduler",…,"dc":{"contract":"scheduler","id":"…","trace":"…"}}
+//! ```
+//!
+//! Readers go through a [`MigrationRegistry`]: each line is upgraded by the
+//! registered per-major migration steps (1→2→3…) until it reaches the
+//! reader's version, then decoded into an [`EvidenceEntry`]. Minor versions
+//! only add fields, so a 2.1 line is readable by a 2.0 reader; the fields it
+//! does not know land in [`EvidenceEntry::unknown`] and are written back
+//! unchanged by [`EvidenceEntry::encode`], so read-modify-write tooling never
+//! strips data written by a newer producer. A line from a newer *major*
+//! version is rejected rather than guessed at.
+//!
+//! During a migration window a [`DualWriteSink`] emits every entry at both the
+//! old and the new version, either to two separate outputs or as one envelope
+//! line (`{"_envelope":{"1.0":{…},"2.0":{…}}}`) from which each reader picks
+//! the newest version it understands. [`validate_corpus`] checks persisted
+//! files against a target version before the old version is retired.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use franken_decision::DecisionAuditEntry;
+use franken_evidence::EvidenceLedger;
+use parking_lot::Mutex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::evidence_sink::EvidenceSink;
+
+/// Key carrying the schema version of a 2.0+ entry.
+const VERSION_KEY: &str = "v";
+
+/// Key carrying the decision-contract fields of a 2.0+ entry.
+const CONTRACT_KEY: &str = "dc";
+
+/// Key of a dual-write envelope line.
+const ENVELOPE_KEY: &str = "_envelope";
+
+/// Serde names of the [`EvidenceLedger`] fields.
+const LEDGER_KEYS: [&str; 9] = ["ts", "c", "a", "p", "el", "cel", "cal", "fb", "tf"];
+
+// ---------------------------------------------------------------------------
+// Versions
+// ---------------------------------------------------------------------------
+
+/// Evidence schema version, `MAJOR.MINOR`.
+///
+/// Minor versions only add fields; a new major version may reshape entries
+/// and needs a registered migration step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion {
+    /// Major version.
+    pub major: u16,
+    /// Minor version.
+    pub minor: u16,
+}
+
+impl SchemaVersion {
+    /// Bare [`EvidenceLedger`] lines.
+    pub const V1: Self = Self::new(1, 0);
+    /// Version-tagged lines with decision-contract fields.
+    pub const V2: Self = Self::new(2, 0);
+    /// Version this build reads into [`EvidenceEntry`] and writes by default.
+    pub const CURRENT: Self = Self::V2;
+
+    /// Creates a version.
+    #[must_use]
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns `true` if entries at this version can be encoded by this build.
+    #[must_use]
+    pub const fn is_writable(self) -> bool {
+        self.major == Self::V1.major || self.major == Self::V2.major
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = SchemaError;
+
+    /// Parses `MAJOR.MINOR`; a trailing `.PATCH` (as in exporter headers) is
+    /// accepted and ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SchemaError::InvalidVersion(s.to_string());
+        let mut parts = s.split('.');
+        let major = parts.next().ok_or_else(invalid)?;
+        let minor = parts.next().ok_or_else(invalid)?;
+        if let Some(patch) = parts.next() {
+            patch.parse::<u32>().map_err(|_| invalid())?;
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self::new(
+            major.parse().map_err(|_| invalid())?,
+            minor.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl Serialize for SchemaVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Error reading, migrating or encoding a versioned evidence entry.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The line is not JSON.
+    #[error("invalid JSON: {0}")]
+    Json(String),
+    /// The line is JSON but not an object.
+    #[error("entry is not a JSON object")]
+    NotAnObject,
+    /// A version tag is not `MAJOR.MINOR`.
+    #[error("invalid schema version {0:?}")]
+    InvalidVersion(String),
+    /// The entry's major version is newer than the reader, or cannot be
+    /// encoded by this build.
+    #[error("unsupported schema version {0}")]
+    UnsupportedVersion(SchemaVersion),
+    /// No registered migration step leads from `from` to `to`.
+    #[error("no migration path from {from} to {to}")]
+    NoMigrationPath {
+        /// Version the entry was stuck at.
+        from: SchemaVersion,
+        /// Requested version.
+        to: SchemaVersion,
+    },
+    /// A migration step rejected the entry.
+    #[error("migration {from} -> {to} failed: {reason}")]
+    Migration {
+        /// Version before the step.
+        from: SchemaVersion,
+        /// Version after the step.
+        to: SchemaVersion,
+        /// Reason reported by the step.
+        reason: String,
+    },
+    /// The migrated entry does not decode at its version.
+    #[error("{0}")]
+    Invalid(String),
+    /// Writing an encoded entry failed.
+    #[error("write failed: {0}")]
+    Io(String),
+}
+
+impl From<serde_json::Error> for SchemaError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error.to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entries
+// ---------------------------------------------------------------------------
+
+/// Decision-contract fields added in schema 2.0.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractFields {
+    /// Name of the decision contract that produced the entry.
+    #[serde(rename = "contract")]
+    pub contract_name: String,
+    /// Decision id, when the producer had one.
+    #[serde(rename = "id", default, skip_serializing_if = "Option::is_none")]
+    pub decision_id: Option<String>,
+    /// Trace id, when the producer had one.
+    #[serde(rename = "trace", default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
+/// Evidence entry in the current in-memory representation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvidenceEntry {
+    /// Schema version of the entry. Entries upgraded from an older major
+    /// version report the version they were upgraded to.
+    pub version: SchemaVersion,
+    /// The 1.0 ledger fields.
+    pub ledger: EvidenceLedger,
+    /// The 2.0 decision-contract fields.
+    pub contract: ContractFields,
+    /// Fields this build does not know, preserved for re-encoding.
+    pub unknown: Map<String, Value>,
+}
+
+/// Borrowed 2.0 line layout: version tag, ledger fields, contract fields,
+/// then preserved unknown fields.
+#[derive(Serialize)]
+struct EncodedV2<'a> {
+    v: SchemaVersion,
+    #[serde(flatten)]
+    ledger: &'a EvidenceLedger,
+    dc: &'a ContractFields,
+    #[serde(flatten)]
+    unknown: &'a Map<String, Value>,
+}
+
+impl EvidenceEntry {
+    /// Wraps a ledger entry; the component name stands in for the contract
+    /// name, as in entries migrated from 1.0.
+    #[must_use]
+    pub fn from_ledger(ledger: EvidenceLedger) -> Self {
+        let contract = ContractFields {
+            contract_name: ledger.component.clone(),
+            decision_id: None,
+            trace_id: None,
+        };
+        Self {
+            version: SchemaVersion::CURRENT,
+            ledger,
+            contract,
+            unknown: Map::new(),
+        }
+    }
+
+    /// Builds an entry from a decision audit record, keeping its ids.
+    #[must_use]
+    pub fn from_audit(audit: &DecisionAuditEntry) -> Self {
+        let mut entry = Self::from_ledger(audit.to_evidence_ledger());
+        entry.contract = ContractFields {
+            contract_name: audit.contract_name.clone(),
+            decision_id: Some(audit.decision_id.to_string()),
+            trace_id: Some(audit.trace_id.to_string()),
+        };
+        entry
+    }
+
+    /// Encodes the entry as one NDJSON line (without the newline) at
+    /// `version`.
+    ///
+    /// Encoding at 1.x drops everything but the ledger fields. Encoding at
+    /// the entry's own major version keeps its minor version if that is
+    /// newer than `version`, together with the unknown fields it carries.
+    pub fn encode(&self, version: SchemaVersion) -> Result<String, SchemaError> {
+        match version.major {
+            1 => Ok(serde_json::to_string(&self.ledger)?),
+            2 => {
+                let v = if self.version.major == version.major {
+                    self.version.max(version)
+                } else {
+                    version
+                };
+                Ok(serde_json::to_string(&EncodedV2 {
+                    v,
+                    ledger: &self.ledger,
+                    dc: &self.contract,
+                    unknown: &self.unknown,
+                })?)
+            }
+            _ => Err(SchemaError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Migrations
+// ---------------------------------------------------------------------------
+
+/// A migration step rewriting an entry object in place.
+///
+/// The registry updates the version tag after the step succeeds.
+pub type MigrationFn = dyn Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync;
+
+/// Chain of per-major migration steps used to read older entries.
+#[derive(Clone)]
+pub struct MigrationRegistry {
+    steps: BTreeMap<u16, (SchemaVersion, Arc<MigrationFn>)>,
+}
+
+impl fmt::Debug for MigrationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|(from, (to, _))| format!("{from}.x -> {to}"))
+            .collect();
+        f.debug_struct("MigrationRegistry")
+            .field("steps", &steps)
+            .finish()
+    }
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MigrationRegistry {
+    /// Registry with the built-in steps up to [`SchemaVersion::CURRENT`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::empty().register(SchemaVersion::V1, SchemaVersion::V2, migrate_v1_to_v2)
+    }
+
+    /// Registry without any steps.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the step upgrading entries of major version `from.major` to
+    /// `to`, replacing any step previously registered for that major.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` does not have a higher major version than `from`.
+    #[must_use]
+    pub fn register<F>(mut self, from: SchemaVersion, to: SchemaVersion, step: F) -> Self
+    where
+        F: Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        assert!(
+            to.major > from.major,
+            "migration must raise the major version: {from} -> {to}"
+        );
+        self.steps.insert(from.major, (to, Arc::new(step)));
+        self
+    }
+
+    /// Upgrades an entry object to the major version of `target`.
+    ///
+    /// Entries already at that major version are returned unchanged,
+    /// whatever their minor version.
+    pub fn upgrade(
+        &self,
+        mut object: Map<String, Value>,
+        target: SchemaVersion,
+    ) -> Result<Map<String, Value>, SchemaError> {
+        let mut version = version_of(&object)?;
+        if version.major > target.major {
+            return Err(SchemaError::UnsupportedVersion(version));
+        }
+        while version.major < target.major {
+            let Some((to, step)) = self
+                .steps
+                .get(&version.major)
+                .filter(|(to, _)| to.major <= target.major)
+            else {
+                return Err(SchemaError::NoMigrationPath {
+                    from: version,
+                    to: target,
+                });
+            };
+            step(&mut object).map_err(|reason| SchemaError::Migration {
+                from: version,
+                to: *to,
+                reason,
+            })?;
+            object.insert(VERSION_KEY.to_string(), Value::String(to.to_string()));
+            version = *to;
+        }
+        Ok(object)
+    }
+
+    /// Reads one NDJSON line into the current representation.
+    ///
+    /// Envelope lines yield their newest version this build can read.
+    pub fn decode_line(&self, line: &str) -> Result<EvidenceEntry, SchemaError> {
+        let object = unwrap_envelope(parse_object(line)?, SchemaVersion::CURRENT)?;
+        decode_current(self.upgrade(object, SchemaVersion::CURRENT)?)
+    }
+
+    /// Checks that one NDJSON line can be read at `target`, returning the
+    /// version it was written at.
+    ///
+    /// Entries upgraded to a major version this build decodes are fully
+    /// validated; for other targets the migration chain alone is checked.
+    pub fn check_line(
+        &self,
+        line: &str,
+        target: SchemaVersion,
+    ) -> Result<SchemaVersion, SchemaError> {
+        let object = unwrap_envelope(parse_object(line)?, target)?;
+        let origin = version_of(&object)?;
+        let object = self.upgrade(object, target)?;
+        match target.major {
+            1 => {
+                ledger_from(object)?;
+            }
+            2 => {
+                decode_current(object)?;
+            }
+            _ => {}
+        }
+        Ok(origin)
+    }
+}
+
+/// Shared registry with the built-in steps, used by the query layer.
+pub(crate) fn builtin_registry() -> &'static MigrationRegistry {
+    static REGISTRY: OnceLock<MigrationRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MigrationRegistry::new)
+}
+
+/// Built-in 1.0 → 2.0 step: the component stands in for the contract name.
+fn migrate_v1_to_v2(object: &mut Map<String, Value>) -> Result<(), String> {
+    let component = object
+        .get("c")
+        .and_then(Value::as_str)
+        .ok_or("missing component `c`")?
+        .to_string();
+    object
+        .entry(CONTRACT_KEY)
+        .or_insert_with(|| serde_json::json!({ "contract": component }));
+    Ok(())
+}
+
+fn parse_object(line: &str) -> Result<Map<String, Value>, SchemaError> {
+    match serde_json::from_str::<Value>(line)? {
+        Value::Object(object) => Ok(object),
+        _ => Err(SchemaError::NotAnObject),
+    }
+}
+
+/// Version tag of an entry object; untagged entries are 1.0.
+fn version_of(object: &Map<String, Value>) -> Result<SchemaVersion, SchemaError> {
+    match object.get(VERSION_KEY) {
+        None => Ok(SchemaVersion::V1),
+        Some(Value::String(tag)) => tag.parse(),
+        Some(other) => Err(SchemaError::InvalidVersion(other.to_string())),
+    }
+}
+
+/// Picks the newest envelope member whose major version is at most
+/// `target`'s; other objects pass through.
+fn unwrap_envelope(
+    mut object: Map<String, Value>,
+    target: SchemaVersion,
+) -> Result<Map<String, Value>, SchemaError> {
+    let Some(members) = object.remove(ENVELOPE_KEY) else {
+        return Ok(object);
+    };
+    let Value::Object(members) = members else {
+        return Err(SchemaError::Invalid("envelope is not an object".to_string()));
+    };
+    let mut best: Option<(SchemaVersion, Map<String, Value>)> = None;
+    for (tag, member) in members {
+        let version: SchemaVersion = tag.parse()?;
+        let Value::Object(member) = member else {
+            return Err(SchemaError::NotAnObject);
+        };
+        let newer = best.as_ref().is_none_or(|(current, _)| version > *current);
+        if version.major <= target.major && newer {
+            best = Some((version, member));
+        }
+    }
+    best.map(|(_, member)| member).ok_or_else(|| {
+        SchemaError::Invalid(format!("envelope has no member readable at {target}"))
+    })
+}
+
+/// Splits the ledger fields out of an entry object.
+fn ledger_from(
+    mut object: Map<String, Value>,
+) -> Result<(EvidenceLedger, Map<String, Value>), SchemaError> {
+    let mut fields = Map::new();
+    for key in LEDGER_KEYS {
+        if let Some(value) = object.remove(key) {
+            fields.insert(key.to_string(), value);
+        }
+    }
+    let ledger = serde_json::from_value(Value::Object(fields))
+        .map_err(|error| SchemaError::Invalid(error.to_string()))?;
+    Ok((ledger, object))
+}
+
+fn decode_current(mut object: Map<String, Value>) -> Result<EvidenceEntry, SchemaError> {
+    let version = version_of(&object)?;
+    object.remove(VERSION_KEY);
+    let contract = object.remove(CONTRACT_KEY).ok_or_else(|| {
+        SchemaError::Invalid("missing decision-contract fields `dc`".to_string())
+    })?;
+    let contract = serde_json::from_value(contract)
+        .map_err(|error| SchemaError::Invalid(format!("invalid `dc`: {error}")))?;
+    let (ledger, unknown) = ledger_from(object)?;
+    Ok(EvidenceEntry {
+        version,
+        ledger,
+        contract,
+        unknown,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Dual-write
+// ---------------------------------------------------------------------------
+
+/// Output of a [`DualWriteSink`] and the versions written to it.
+struct WriteTarget {
+    versions: Vec<SchemaVersion>,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl WriteTarget {
+    fn write(&self, entry: &EvidenceEntry) -> Result<(), SchemaError> {
+        let line = if let [version] = self.versions.as_slice() {
+            entry.encode(*version)?
+        } else {
+            let members = self
+                .versions
+                .iter()
+                .map(|version| Ok(format!("\"{version}\":{}", entry.encode(*version)?)))
+                .collect::<Result<Vec<_>, SchemaError>>()?;
+            format!("{{\"{ENVELOPE_KEY}\":{{{}}}}}", members.join(","))
+        };
+        let mut out = self.out.lock();
+        writeln!(out, "{line}")
+            .and_then(|()| out.flush())
+            .map_err(|error| SchemaError::Io(error.to_string()))
+    }
+}
+
+/// Evidence sink writing every entry at two schema versions.
+///
+/// Used for the window in which readers of the old version are still
+/// deployed: [`split`](Self::split) keeps one output per version, while
+/// [`envelope`](Self::envelope) writes both encodings on one line. Entries
+/// are flushed after every write, as with
+/// [`JsonlSink`](crate::evidence_sink::JsonlSink).
+pub struct DualWriteSink {
+    targets: Vec<WriteTarget>,
+    timestamp_seq: AtomicU64,
+}
+
+impl fmt::Debug for DualWriteSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let targets: Vec<&[SchemaVersion]> = self
+            .targets
+            .iter()
+            .map(|target| target.versions.as_slice())
+            .collect();
+        f.debug_struct("DualWriteSink")
+            .field("targets", &targets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DualWriteSink {
+    /// Writes `legacy` lines to `legacy_out` and `current` lines to
+    /// `current_out`.
+    pub fn split<L, C>(
+        legacy: SchemaVersion,
+        legacy_out: L,
+        current: SchemaVersion,
+        current_out: C,
+    ) -> Result<Self, SchemaError>
+    where
+        L: Write + Send + 'static,
+        C: Write + Send + 'static,
+    {
+        Self::with_targets(vec![
+            (vec![legacy], Box::new(legacy_out) as Box<dyn Write + Send>),
+            (vec![current], Box::new(current_out)),
+        ])
+    }
+
+    /// Writes one envelope line per entry holding both encodings.
+    pub fn envelope<W>(
+        legacy: SchemaVersion,
+        current: SchemaVersion,
+        out: W,
+    ) -> Result<Self, SchemaError>
+    where
+        W: Write + Send + 'static,
+    {
+        Self::with_targets(vec![(vec![legacy, current], Box::new(out))])
+    }
+
+    fn with_targets(
+        targets: Vec<(Vec<SchemaVersion>, Box<dyn Write + Send>)>,
+    ) -> Result<Self, SchemaError> {
+        let targets = targets
+            .into_iter()
+            .map(|(versions, out)| {
+                if let Some(version) = versions.iter().find(|v| !v.is_writable()) {
+                    return Err(SchemaError::UnsupportedVersion(*version));
+                }
+                Ok(WriteTarget {
+                    versions,
+                    out: Mutex::new(out),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            targets,
+            timestamp_seq: AtomicU64::new(0),
+        })
+    }
+
+    /// Writes `entry` to every output.
+    ///
+    /// All outputs are attempted; the first failure is returned.
+    pub fn write_entry(&self, entry: &EvidenceEntry) -> Result<(), SchemaError> {
+        let mut result = Ok(());
+        for target in &self.targets {
+            let written = target.write(entry);
+            if result.is_ok() {
+                result = written;
+            }
+        }
+        result
+    }
+}
+
+impl EvidenceSink for DualWriteSink {
+    fn emit(&self, entry: &EvidenceLedger) {
+        if let Err(e) = self.write_entry(&EvidenceEntry::from_ledger(entry.clone())) {
+            // Best-effort, like JsonlSink: evidence loss must not affect the
+            // runtime.
+            #[cfg(feature = "tracing-integration")]
+            crate::tracing_compat::warn!(error = %e, "evidence dual-write failed");
+            let _ = e;
+        }
+    }
+
+    fn next_evidence_ts(&self) -> u64 {
+        // See JsonlSink::next_evidence_ts for why this wraps.
+        self.timestamp_seq
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Corpus validation
+// ---------------------------------------------------------------------------
+
+/// An entry that would fail to read at the target version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusFailure {
+    /// File containing the entry.
+    pub file: String,
+    /// 1-based line number.
+    pub line: u64,
+    /// Failure reason.
+    pub error: String,
+}
+
+/// Result of [`validate_corpus`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusReport {
+    /// Version the corpus was checked against.
+    pub target: SchemaVersion,
+    /// Files read.
+    pub files_scanned: usize,
+    /// Entries checked (header and blank lines excluded).
+    pub entries_checked: u64,
+    /// Readable entries by the version they were written at.
+    pub versions: BTreeMap<SchemaVersion, u64>,
+    /// Entries that would fail.
+    pub failures: Vec<CorpusFailure>,
+}
+
+impl CorpusReport {
+    /// Returns `true` if every entry can be read at the target version.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Checks every entry of the given NDJSON evidence files against `target`.
+///
+/// I/O errors abort the check; entries that are malformed or cannot be
+/// migrated to `target` are listed in [`CorpusReport::failures`].
+pub fn validate_corpus<I, P>(
+    registry: &MigrationRegistry,
+    target: SchemaVersion,
+    files: I,
+) -> io::Result<CorpusReport>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut report = CorpusReport {
+        target,
+        files_scanned: 0,
+        entries_checked: 0,
+        versions: BTreeMap::new(),
+        failures: Vec::new(),
+    };
+    for path in files {
+        let path = path.as_ref();
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        report.files_scanned += 1;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || text.contains("\"_schema\"") {
+                continue;
+            }
+            report.entries_checked += 1;
+            match registry.check_line(text, target) {
+                Ok(origin) => *report.versions.entry(origin).or_default() += 1,
+                Err(error) => report.failures.push(CorpusFailure {
+                    file: path.display().to_string(),
+                    line: index as u64 + 1,
+                    error: error.to_string(),
+                }),
+            }
+        }
+    }
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::Query;
+    use franken_evidence::EvidenceLedgerBuilder;
+
+    /// A 1.0 line as written by `JsonlSink`.
+    const V1_LINE: &str = r#"{"ts":100,"c":"scheduler","a":"preempt","p":[0.6,0.4],"el":{"preempt":0.2},"cel":0.2,"cal":0.9,"fb":false,"tf":[["rtt_ms",12.0]]}"#;
+
+    fn ledger() -> EvidenceLedger {
+        EvidenceLedgerBuilder::new()
+            .ts_unix_ms(100)
+            .component("scheduler")
+            .action("preempt")
+            .posterior(vec![0.6, 0.4])
+            .expected_loss("preempt", 0.2)
+            .chosen_expected_loss(0.2)
+            .calibration_score(0.9)
+            .fallback_active(false)
+            .top_feature("rtt_ms", 12.0)
+            .build()
+            .unwrap()
+    }
+
+    /// The ledger fields of [`V1_LINE`] tagged with `version`, followed by
+    /// `rest`.
+    fn tagged(version: &str, rest: &str) -> String {
+        let body = &V1_LINE[1..V1_LINE.len() - 1];
+        format!("{{\"v\":\"{version}\",{body}{rest}}}")
+    }
+
+    fn object(line: &str) -> Map<String, Value> {
+        parse_object(line).unwrap()
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuf {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn migration_chain_upgrades_each_historical_version() {
+        let v3 = SchemaVersion::new(3, 0);
+        // 3.0 hoists the contract name to the top level.
+        let registry = MigrationRegistry::new().register(SchemaVersion::V2, v3, |object| {
+            let dc = object.remove("dc").ok_or("missing dc")?;
+            let contract = dc.get("contract").cloned().ok_or("missing contract")?;
+            object.insert("contract".to_string(), contract);
+            Ok(())
+        });
+        let v2_line = tagged("2.0", r#","dc":{"contract":"planner","id":"d-1"}"#);
+        let v3_line = tagged("3.0", r#","contract":"planner""#);
+
+        let from_v1 = registry.upgrade(object(V1_LINE), v3).unwrap();
+        assert_eq!(from_v1["v"], "3.0");
+        assert_eq!(from_v1["contract"], "scheduler");
+        assert!(!from_v1.contains_key("dc"));
+        let from_v2 = registry.upgrade(object(&v2_line), v3).unwrap();
+        assert_eq!(from_v2["contract"], "planner");
+        assert_eq!(registry.upgrade(object(&v3_line), v3).unwrap(), object(&v3_line));
+
+        // Stopping at 2.0 runs only the first step.
+        let to_v2 = registry.upgrade(object(V1_LINE), SchemaVersion::V2).unwrap();
+        assert_eq!(to_v2["v"], "2.0");
+        assert_eq!(to_v2["dc"], serde_json::json!({ "contract": "scheduler" }));
+
+        let missing_dc = tagged("2.0", "");
+        assert_eq!(
+            registry.upgrade(object(&missing_dc), v3),
+            Err(SchemaError::Migration {
+                from: SchemaVersion::V2,
+                to: v3,
+                reason: "missing dc".to_string(),
+            })
+        );
+        assert_eq!(
+            MigrationRegistry::empty().upgrade(object(V1_LINE), v3),
+            Err(SchemaError::NoMigrationPath {
+                from: SchemaVersion::V1,
+                to: v3,
+            })
+        );
+
+        // The current reader decodes 1.0 and 2.0 but refuses 3.0.
+        let registry = MigrationRegistry::new();
+        let entry = registry.decode_line(V1_LINE).unwrap();
+        assert_eq!(entry.version, SchemaVersion::V2);
+        assert_eq!(entry.ledger, ledger());
+        assert_eq!(entry.contract.contract_name, "scheduler");
+        assert_eq!(entry.contract.decision_id, None);
+        let entry = registry.decode_line(&v2_line).unwrap();
+        assert_eq!(entry.ledger, ledger());
+        assert_eq!(entry.contract.contract_name, "planner");
+        assert_eq!(entry.contract.decision_id.as_deref(), Some("d-1"));
+        assert_eq!(
+            registry.decode_line(&v3_line),
+            Err(SchemaError::UnsupportedVersion(v3))
+        );
+    }
+
+    #[test]
+    fn dual_write_outputs_are_byte_exact() {
+        let mut entry = EvidenceEntry::from_ledger(ledger());
+        entry.contract.decision_id = Some("d-1".to_string());
+        entry.contract.trace_id = Some("t-1".to_string());
+        let v2_line = tagged(
+            "2.0",
+            r#","dc":{"contract":"scheduler","id":"d-1","trace":"t-1"}"#,
+        );
+
+        let legacy = SharedBuf::default();
+        let current = SharedBuf::default();
+        let sink = DualWriteSink::split(
+            SchemaVersion::V1,
+            legacy.clone(),
+            SchemaVersion::V2,
+            current.clone(),
+        )
+        .unwrap();
+        sink.write_entry(&entry).unwrap();
+        assert_eq!(legacy.contents(), format!("{V1_LINE}\n"));
+        assert_eq!(current.contents(), format!("{v2_line}\n"));
+        // The legacy output is exactly what `JsonlSink` writes.
+        assert_eq!(
+            legacy.contents(),
+            format!("{}\n", serde_json::to_string(&ledger()).unwrap())
+        );
+
+        let combined = SharedBuf::default();
+        let sink =
+            DualWriteSink::envelope(SchemaVersion::V1, SchemaVersion::V2, combined.clone())
+                .unwrap();
+        sink.write_entry(&entry).unwrap();
+        let line = format!("{{\"_envelope\":{{\"1.0\":{V1_LINE},\"2.0\":{v2_line}}}}}");
+        assert_eq!(combined.contents(), format!("{line}\n"));
+
+        // Readers pick the newest member they understand.
+        let registry = MigrationRegistry::new();
+        assert_eq!(registry.decode_line(&line).unwrap(), entry);
+        assert_eq!(registry.check_line(&line, SchemaVersion::V1), Ok(SchemaVersion::V1));
+
+        // The sink interface writes entries with contract name = component.
+        let legacy = SharedBuf::default();
+        let current = SharedBuf::default();
+        let sink = DualWriteSink::split(
+            SchemaVersion::V1,
+            legacy.clone(),
+            SchemaVersion::V2,
+            current.clone(),
+        )
+        .unwrap();
+        sink.emit(&ledger());
+        assert_eq!(legacy.contents(), format!("{V1_LINE}\n"));
+        assert_eq!(
+            current.contents(),
+            format!("{}\n", tagged("2.0", r#","dc":{"contract":"scheduler"}"#))
+        );
+
+        let unwritable = DualWriteSink::envelope(
+            SchemaVersion::V1,
+            SchemaVersion::new(3, 0),
+            SharedBuf::default(),
+        );
+        assert_eq!(
+            unwritable.err(),
+            Some(SchemaError::UnsupportedVersion(SchemaVersion::new(3, 0)))
+        );
+    }
+
+    #[test]
+    fn unknown_fields_survive_read_modify_write() {
+        let line = tagged(
+            "2.1",
+            r#","dc":{"contract":"scheduler"},"region":{"tier":3},"zz":[1,"a"]"#,
+        );
+        let registry = MigrationRegistry::new();
+        let mut entry = registry.decode_line(&line).unwrap();
+        assert_eq!(entry.version, SchemaVersion::new(2, 1));
+        assert_eq!(
+            entry.unknown.keys().collect::<Vec<_>>(),
+            vec!["region", "zz"]
+        );
+
+        entry.contract.trace_id = Some("t-9".to_string());
+        let rewritten = entry.encode(SchemaVersion::CURRENT).unwrap();
+        let rewritten = object(&rewritten);
+        let original = object(&line);
+        assert_eq!(rewritten["v"], "2.1", "newer minor version is kept");
+        assert_eq!(rewritten["region"], original["region"]);
+        assert_eq!(rewritten["zz"], original["zz"]);
+        assert_eq!(rewritten["dc"]["trace"], "t-9");
+
+        let reread = registry.decode_line(&entry.encode(SchemaVersion::V2).unwrap());
+        assert_eq!(reread.unwrap(), entry);
+        // Downgrading to 1.0 keeps only the ledger fields.
+        assert_eq!(entry.encode(SchemaVersion::V1).unwrap(), V1_LINE);
+    }
+
+    #[test]
+    fn corpus_validation_reports_failing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.jsonl");
+        let second = dir.path().join("second.jsonl");
+        let header = r#"{"_schema":"EvidenceLedger","_version":"1.0.0"}"#;
+        let v2_line = tagged("2.0", r#","dc":{"contract":"scheduler"}"#);
+        std::fs::write(&first, format!("{header}\n{V1_LINE}\n{v2_line}\n")).unwrap();
+        let lines = [
+            tagged("3.0", ""),
+            "not json".to_string(),
+            String::new(),
+            tagged("2.0", ""),
+            tagged("2.1", r#","dc":{"contract":"scheduler"},"extra":true"#),
+        ];
+        std::fs::write(&second, lines.join("\n")).unwrap();
+
+        let registry = MigrationRegistry::new();
+        let report = validate_corpus(&registry, SchemaVersion::V2, [&first, &second]).unwrap();
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.entries_checked, 6);
+        assert_eq!(
+            report.versions,
+            BTreeMap::from([
+                (SchemaVersion::V1, 1),
+                (SchemaVersion::V2, 1),
+                (SchemaVersion::new(2, 1), 1),
+            ])
+        );
+        let second_name = second.display().to_string();
+        let failed: Vec<_> = report
+            .failures
+            .iter()
+            .map(|failure| (failure.file.as_str(), failure.line))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                (second_name.as_str(), 1),
+                (second_name.as_str(), 2),
+                (second_name.as_str(), 4),
+            ]
+        );
+        assert_eq!(report.failures[0].error, "unsupported schema version 3.0");
+        assert!(report.failures[2].error.contains("`dc`"));
+        assert!(!report.is_clean());
+
+        // Against 1.0 every tagged entry fails; the 1.0 entry still passes.
+        let report = validate_corpus(&registry, SchemaVersion::V1, [&first, &second]).unwrap();
+        assert_eq!(report.versions, BTreeMap::from([(SchemaVersion::V1, 1)]));
+        assert_eq!(report.failures.len(), 5);
+
+        let clean = validate_corpus(&registry, SchemaVersion::V2, [&first]).unwrap();
+        assert!(clean.is_clean());
+        let json = serde_json::to_value(&clean).unwrap();
+        assert_eq!(json["target"], "2.0");
+        assert_eq!(json["versions"]["1.0"], 1);
+    }
+
+    #[test]
+    fn query_reads_mixed_version_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.jsonl");
+        let migrating = dir.path().join("migrating.jsonl");
+        let header = r#"{"_schema":"EvidenceLedger","_version":"1.0.0"}"#;
+        std::fs::write(&old, format!("{header}\n{V1_LINE}\n")).unwrap();
+
+        let out = SharedBuf::default();
+        let sink =
+            DualWriteSink::envelope(SchemaVersion::V1, SchemaVersion::V2, out.clone()).unwrap();
+        sink.emit(&ledger());
+        let v2_line = tagged("2.0", r#","dc":{"contract":"scheduler"}"#);
+        let future_minor = tagged("2.3", r#","dc":{"contract":"scheduler"},"later":1"#);
+        std::fs::write(
+            &migrating,
+            format!("{v2_line}\n{}{future_minor}\n", out.contents()),
+        )
+        .unwrap();
+
+        let output = Query::new()
+            .component("scheduler")
+            .field_eq("fallback_active", false)
+            .execute([&old, &migrating])
+            .unwrap();
+        assert!(output.summary.malformed.is_empty(), "{:?}", output.summary);
+        assert_eq!(output.entries, vec![ledger(); 4]);
+    }
+}
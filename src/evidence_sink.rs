@@ -8,17 +8,31 @@
 //! # Backends
 //!
 //! - [`NullSink`]: No-op (zero overhead when evidence collection is disabled).
-//! - [`JsonlSink`]: Appends to a JSONL file via [`franken_evidence::export::JsonlExporter`].
+//! - [`JsonlSink`]: Appends to a JSONL file via [`franken_evidence::export::JsonlExporter`],
+//!   optionally maintaining the sidecar index used by [`crate::evidence::Query`].
 //! - [`CollectorSink`]: In-memory collection for testing.
+//! - [`RingBufferSink`]: Batches entries in front of another sink; flushable
+//!   from a crash hook.
+//! - [`AdaptiveBatchSink`]: Batches entries in front of a [`BatchExporter`],
+//!   sizing batches and the flush interval from downstream latency and the
+//!   arrival rate.
 
 use parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use franken_evidence::EvidenceLedger;
 use franken_evidence::export::JsonlExporter;
 
+use crate::combinator::adaptive_concurrency::{LimitAlgorithm, LimitOutcome, LimitSample};
+use crate::evidence::{EvidenceIndex, EvidenceIndexBuilder};
+use crate::time::{TimeSource, WallClock};
+use crate::types::Time;
+
 // ---------------------------------------------------------------------------
 // Trait
 // ---------------------------------------------------------------------------
@@ -67,6 +81,7 @@ impl EvidenceSink for NullSink {
 /// Flush is called after every write to ensure durability.
 pub struct JsonlSink {
     inner: Mutex<JsonlExporter>,
+    index: Option<Mutex<EvidenceIndexBuilder>>,
     timestamp_seq: AtomicU64,
 }
 
@@ -74,6 +89,7 @@ impl fmt::Debug for JsonlSink {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("JsonlSink")
             .field("path", &self.path())
+            .field("indexed", &self.index.is_some())
             .finish()
     }
 }
@@ -86,19 +102,52 @@ impl JsonlSink {
         let exporter = JsonlExporter::open(path)?;
         Ok(Self {
             inner: Mutex::new(exporter),
+            index: None,
             timestamp_seq: AtomicU64::new(0),
         })
     }
 
+    /// Open a JSONL sink that also maintains a query index for its file.
+    ///
+    /// Entries already in the file are indexed on open. The sidecar consulted
+    /// by [`Query`](crate::evidence::Query) is written by
+    /// [`sync_index`](Self::sync_index); appends after a sync make it stale,
+    /// which queries detect and ignore. After a rotation the index keeps
+    /// covering the rotated-out entries, so it only ever over-approximates.
+    pub fn open_indexed(path: PathBuf) -> std::io::Result<Self> {
+        let builder = EvidenceIndexBuilder::from_file(&path)?;
+        let mut sink = Self::open(path)?;
+        sink.index = Some(Mutex::new(builder));
+        Ok(sink)
+    }
+
     /// Path to the current output file.
     pub fn path(&self) -> PathBuf {
         self.inner.lock().path().to_path_buf()
     }
+
+    /// Write the sidecar query index for the current file.
+    ///
+    /// No-op for sinks opened without an index.
+    pub fn sync_index(&self) -> std::io::Result<()> {
+        let Some(index) = self.index.as_ref() else {
+            return Ok(());
+        };
+        let mut exporter = self.inner.lock();
+        exporter.flush()?;
+        let indexed_bytes = std::fs::metadata(exporter.path())?.len();
+        let snapshot: EvidenceIndex = index.lock().finish(indexed_bytes);
+        snapshot.write_sidecar(exporter.path())
+    }
 }
 
 impl EvidenceSink for JsonlSink {
     fn emit(&self, entry: &EvidenceLedger) {
         let mut exporter = self.inner.lock();
+        if let Some(index) = self.index.as_ref() {
+            // Index before writing so the index never misses a persisted entry.
+            index.lock().observe(entry);
+        }
         if let Err(e) = exporter.append(entry).and_then(|_| exporter.flush()) {
             // Best-effort: log and continue. Evidence loss is acceptable;
             // runtime correctness must not depend on evidence collection.
@@ -174,6 +223,496 @@ impl EvidenceSink for CollectorSink {
     }
 }
 
+// ---------------------------------------------------------------------------
+// RingBufferSink
+// ---------------------------------------------------------------------------
+
+/// Bounded in-memory buffer in front of another sink.
+///
+/// Entries collect in a ring of `capacity` and are written to the inner sink
+/// in one batch when the ring fills or on [`flush`](Self::flush). Keeps
+/// per-entry file I/O off hot paths; the crash report hook of
+/// [`crate::runtime::panic_strategy`] flushes it before a `panic = "abort"`
+/// process dies so buffered entries are not lost.
+pub struct RingBufferSink {
+    inner: Arc<dyn EvidenceSink>,
+    buffer: Mutex<VecDeque<EvidenceLedger>>,
+    capacity: usize,
+}
+
+impl fmt::Debug for RingBufferSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBufferSink")
+            .field("inner", &self.inner)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RingBufferSink {
+    /// Buffers up to `capacity` entries (at least one) in front of `inner`.
+    #[must_use]
+    pub fn new(inner: Arc<dyn EvidenceSink>, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner,
+            buffer: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Number of entries waiting to be flushed.
+    pub fn buffered(&self) -> usize {
+        self.buffer.lock().len()
+    }
+
+    /// Writes every buffered entry to the inner sink. Returns how many.
+    pub fn flush(&self) -> usize {
+        let mut buffer = self.buffer.lock();
+        self.drain(&mut buffer)
+    }
+
+    /// Like [`flush`](Self::flush), but gives up after `timeout` if the
+    /// buffer is locked, returning `None`. For crash paths, where the lock
+    /// holder may be the thread that is going down.
+    pub fn try_flush_for(&self, timeout: Duration) -> Option<usize> {
+        let mut buffer = self.buffer.try_lock_for(timeout)?;
+        Some(self.drain(&mut buffer))
+    }
+
+    fn drain(&self, buffer: &mut VecDeque<EvidenceLedger>) -> usize {
+        let count = buffer.len();
+        for entry in buffer.drain(..) {
+            self.inner.emit(&entry);
+        }
+        count
+    }
+}
+
+impl EvidenceSink for RingBufferSink {
+    fn emit(&self, entry: &EvidenceLedger) {
+        let mut buffer = self.buffer.lock();
+        buffer.push_back(entry.clone());
+        if buffer.len() >= self.capacity {
+            self.drain(&mut buffer);
+        }
+    }
+
+    fn next_evidence_ts(&self) -> u64 {
+        self.inner.next_evidence_ts()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AdaptiveBatchSink
+// ---------------------------------------------------------------------------
+
+/// Downstream of an [`AdaptiveBatchSink`]: receives whole batches and reports
+/// whether they were accepted.
+pub trait BatchExporter: Send + Sync + fmt::Debug {
+    /// Exports `batch`, oldest entry first.
+    ///
+    /// On error the sink keeps the batch and retries it, in order, on a later
+    /// flush.
+    fn export(&self, batch: &[EvidenceLedger]) -> std::io::Result<()>;
+}
+
+impl BatchExporter for Arc<dyn EvidenceSink> {
+    fn export(&self, batch: &[EvidenceLedger]) -> std::io::Result<()> {
+        for entry in batch {
+            self.emit(entry);
+        }
+        Ok(())
+    }
+}
+
+/// Multiplicative batch-size controller for [`AdaptiveBatchSink`].
+///
+/// Implements the [`LimitAlgorithm`] interface of adaptive concurrency
+/// limiting, with the batch size as the limit and the number of exported
+/// entries as `in_flight`: the size is multiplied by `growth_factor` after an
+/// export that finishes within `latency_target` with the batch at least half
+/// full, and by `backoff_ratio` after a slow or failed export.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchGrowth {
+    /// Factor applied to the batch size after a fast export (> 1).
+    pub growth_factor: f64,
+    /// Factor applied to the batch size after a slow or failed export (0, 1).
+    pub backoff_ratio: f64,
+    /// Exports slower than this count as a downstream breach.
+    pub latency_target: Duration,
+}
+
+impl Default for BatchGrowth {
+    fn default() -> Self {
+        Self {
+            growth_factor: 2.0,
+            backoff_ratio: 0.5,
+            latency_target: Duration::from_millis(50),
+        }
+    }
+}
+
+impl LimitAlgorithm for BatchGrowth {
+    fn update(&mut self, limit: u32, sample: &LimitSample) -> u32 {
+        let breached = match sample.outcome {
+            LimitOutcome::Failure => true,
+            LimitOutcome::Success => sample.latency > self.latency_target,
+            LimitOutcome::Ignore => return limit,
+        };
+        if breached {
+            let reduced = (f64::from(limit) * self.backoff_ratio) as u32;
+            reduced.min(limit.saturating_sub(1))
+        } else if sample.in_flight.saturating_mul(2) >= limit {
+            // A mostly empty batch says nothing about larger ones.
+            let grown = (f64::from(limit) * self.growth_factor) as u32;
+            grown.max(limit.saturating_add(1))
+        } else {
+            limit
+        }
+    }
+}
+
+/// Bounds for an [`AdaptiveBatchSink`]'s controller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveBatchConfig {
+    /// Batch size before any export is observed.
+    pub initial_batch_size: u32,
+    /// Lower bound for the batch size (at least 1).
+    pub min_batch_size: u32,
+    /// Upper bound for the batch size.
+    pub max_batch_size: u32,
+    /// Shortest time an entry waits for its batch to fill.
+    pub min_interval: Duration,
+    /// Longest time an entry waits for its batch to fill.
+    pub max_interval: Duration,
+}
+
+impl AdaptiveBatchConfig {
+    /// Sets the initial batch size.
+    #[must_use]
+    pub fn initial_batch_size(mut self, size: u32) -> Self {
+        self.initial_batch_size = size;
+        self
+    }
+
+    /// Sets the batch size bounds.
+    #[must_use]
+    pub fn batch_bounds(mut self, min: u32, max: u32) -> Self {
+        self.min_batch_size = min;
+        self.max_batch_size = max;
+        self
+    }
+
+    /// Sets the flush interval bounds.
+    #[must_use]
+    pub fn interval_bounds(mut self, min: Duration, max: Duration) -> Self {
+        self.min_interval = min;
+        self.max_interval = max;
+        self
+    }
+
+    fn clamp_batch_size(&self, size: u32) -> u32 {
+        let min = self.min_batch_size.max(1);
+        size.clamp(min, self.max_batch_size.max(min))
+    }
+
+    fn intervals(&self) -> (Duration, Duration) {
+        (self.min_interval, self.max_interval.max(self.min_interval))
+    }
+}
+
+impl Default for AdaptiveBatchConfig {
+    fn default() -> Self {
+        Self {
+            initial_batch_size: 64,
+            min_batch_size: 1,
+            max_batch_size: 4_096,
+            min_interval: Duration::from_millis(10),
+            max_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Controller state and counters of an [`AdaptiveBatchSink`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveBatchMetrics {
+    /// Current batch size.
+    pub batch_size: u32,
+    /// Current flush interval.
+    pub flush_interval: Duration,
+    /// Latency of the most recent export attempt.
+    pub last_latency: Option<Duration>,
+    /// Entries waiting to be exported.
+    pub buffered: usize,
+    /// Batches accepted by the exporter.
+    pub batches_exported: u64,
+    /// Entries accepted by the exporter.
+    pub entries_exported: u64,
+    /// Export attempts that failed; their entries stayed buffered.
+    pub failed_exports: u64,
+    /// Number of batch size increases.
+    pub increases: u64,
+    /// Number of batch size decreases.
+    pub decreases: u64,
+}
+
+#[derive(Debug)]
+struct BatchState {
+    entries: VecDeque<EvidenceLedger>,
+    arrivals: VecDeque<Time>,
+    algorithm: Box<dyn LimitAlgorithm>,
+    last_arrival: Option<Time>,
+    mean_gap_nanos: Option<u64>,
+    retry_at: Option<Time>,
+    metrics: AdaptiveBatchMetrics,
+}
+
+impl BatchState {
+    fn record_arrival(&mut self, now: Time) {
+        if let Some(previous) = self.last_arrival {
+            let gap = now.duration_since(previous);
+            let mean = self
+                .mean_gap_nanos
+                .map_or(gap, |mean| mean - mean / 8 + gap / 8);
+            self.mean_gap_nanos = Some(mean);
+        }
+        self.last_arrival = Some(now);
+    }
+
+    /// Waits as long as filling the batch takes at the observed arrival rate.
+    /// When even `max_interval` would not fill it, waiting buys little
+    /// batching, so the interval falls with the rate instead.
+    fn update_interval(&mut self, config: &AdaptiveBatchConfig) {
+        let (min, max) = config.intervals();
+        let Some(gap) = self.mean_gap_nanos else {
+            self.metrics.flush_interval = min;
+            return;
+        };
+        let max_nanos = max.as_nanos();
+        let fill = u128::from(gap) * u128::from(self.metrics.batch_size);
+        let nanos = if fill <= max_nanos {
+            fill
+        } else {
+            max_nanos * max_nanos / fill
+        };
+        let interval = Duration::from_nanos(nanos.min(u128::from(u64::MAX)) as u64);
+        self.metrics.flush_interval = interval.clamp(min, max);
+    }
+
+    fn is_due(&self, now: Time) -> bool {
+        if self.retry_at.is_some_and(|at| now < at) {
+            return false;
+        }
+        self.entries.len() >= self.metrics.batch_size as usize
+            || self
+                .arrivals
+                .front()
+                .is_some_and(|&arrived| now >= arrived + self.metrics.flush_interval)
+    }
+}
+
+/// Evidence sink that batches entries in front of a [`BatchExporter`] and
+/// adapts the batching to the downstream.
+///
+/// A batch is exported when it reaches the current batch size or when its
+/// oldest entry has waited the current flush interval. Every export feeds its
+/// latency and outcome to a [`LimitAlgorithm`] ([`BatchGrowth`] by default)
+/// that sizes the next batch within the configured bounds. The flush interval
+/// follows the arrival rate: at high rates it is the time a batch takes to
+/// fill, at low rates entries are exported close to `min_interval`.
+///
+/// A failed batch stays at the head of the buffer and is retried, in order,
+/// once the flush interval has passed; entries are never dropped or
+/// reordered. Exports run on the emitting thread; call [`tick`](Self::tick)
+/// at [`next_flush_at`](Self::next_flush_at) to export batches whose interval
+/// expires between emits. All times come from the configured
+/// [`TimeSource`], so a lab run with a virtual clock replays the same
+/// adaptation.
+pub struct AdaptiveBatchSink {
+    exporter: Arc<dyn BatchExporter>,
+    config: AdaptiveBatchConfig,
+    clock: Arc<dyn TimeSource>,
+    state: Mutex<BatchState>,
+    timestamp_seq: AtomicU64,
+}
+
+impl fmt::Debug for AdaptiveBatchSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdaptiveBatchSink")
+            .field("exporter", &self.exporter)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdaptiveBatchSink {
+    /// Batches entries in front of `exporter`, sized by `algorithm`.
+    #[must_use]
+    pub fn new(
+        exporter: Arc<dyn BatchExporter>,
+        config: AdaptiveBatchConfig,
+        algorithm: impl LimitAlgorithm + 'static,
+    ) -> Self {
+        let mut state = BatchState {
+            entries: VecDeque::new(),
+            arrivals: VecDeque::new(),
+            algorithm: Box::new(algorithm),
+            last_arrival: None,
+            mean_gap_nanos: None,
+            retry_at: None,
+            metrics: AdaptiveBatchMetrics {
+                batch_size: config.clamp_batch_size(config.initial_batch_size),
+                ..AdaptiveBatchMetrics::default()
+            },
+        };
+        state.update_interval(&config);
+        Self {
+            exporter,
+            config,
+            clock: Arc::new(WallClock::new()),
+            state: Mutex::new(state),
+            timestamp_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Replaces the clock used for arrival times, deadlines and latencies.
+    #[must_use]
+    pub fn with_time_source(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the controller bounds.
+    #[must_use]
+    pub fn config(&self) -> &AdaptiveBatchConfig {
+        &self.config
+    }
+
+    /// Returns the controller state and counters.
+    #[must_use]
+    pub fn metrics(&self) -> AdaptiveBatchMetrics {
+        let state = self.state.lock();
+        AdaptiveBatchMetrics {
+            buffered: state.entries.len(),
+            ..state.metrics.clone()
+        }
+    }
+
+    /// Number of entries waiting to be exported.
+    pub fn buffered(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    /// When the oldest buffered entry's flush interval expires.
+    pub fn next_flush_at(&self) -> Option<Time> {
+        let state = self.state.lock();
+        let deadline = *state.arrivals.front()? + state.metrics.flush_interval;
+        Some(state.retry_at.map_or(deadline, |at| deadline.max(at)))
+    }
+
+    /// Exports every batch that is due. Returns how many entries were
+    /// exported.
+    pub fn tick(&self) -> usize {
+        let mut state = self.state.lock();
+        self.export_due(&mut state)
+    }
+
+    /// Exports every buffered entry, stopping at the first failed batch.
+    /// Returns how many entries were exported.
+    pub fn flush(&self) -> usize {
+        let mut state = self.state.lock();
+        let mut exported = 0;
+        while !state.entries.is_empty() {
+            match self.export_batch(&mut state) {
+                Some(count) => exported += count,
+                None => break,
+            }
+        }
+        exported
+    }
+
+    fn export_due(&self, state: &mut BatchState) -> usize {
+        let mut exported = 0;
+        while state.is_due(self.clock.now()) {
+            match self.export_batch(state) {
+                Some(count) => exported += count,
+                None => break,
+            }
+        }
+        exported
+    }
+
+    /// Exports the head batch and adapts the controller to the outcome.
+    /// Returns the exported entry count, or `None` if the exporter failed.
+    fn export_batch(&self, state: &mut BatchState) -> Option<usize> {
+        let count = state.entries.len().min(state.metrics.batch_size as usize);
+        let started = self.clock.now();
+        let result = self
+            .exporter
+            .export(&state.entries.make_contiguous()[..count]);
+        let finished = self.clock.now();
+        let latency = Duration::from_nanos(finished.duration_since(started));
+
+        let outcome = if result.is_ok() {
+            state.entries.drain(..count);
+            state.arrivals.drain(..count);
+            state.retry_at = None;
+            state.metrics.batches_exported += 1;
+            state.metrics.entries_exported += count as u64;
+            LimitOutcome::Success
+        } else {
+            state.metrics.failed_exports += 1;
+            LimitOutcome::Failure
+        };
+        let sample = LimitSample {
+            latency,
+            in_flight: count as u32,
+            outcome,
+        };
+        let previous = state.metrics.batch_size;
+        let next = state.algorithm.update(previous, &sample);
+        let next = self.config.clamp_batch_size(next);
+        if next > previous {
+            state.metrics.increases += 1;
+        } else if next < previous {
+            state.metrics.decreases += 1;
+        }
+        state.metrics.batch_size = next;
+        state.metrics.last_latency = Some(latency);
+        state.update_interval(&self.config);
+
+        if let Err(error) = result {
+            state.retry_at = Some(finished + state.metrics.flush_interval);
+            // Best-effort, like JsonlSink: the batch is kept for a retry.
+            #[cfg(feature = "tracing-integration")]
+            crate::tracing_compat::warn!(error = %error, "evidence batch export failed");
+            let _ = error;
+            return None;
+        }
+        Some(count)
+    }
+}
+
+impl EvidenceSink for AdaptiveBatchSink {
+    fn emit(&self, entry: &EvidenceLedger) {
+        let now = self.clock.now();
+        let mut state = self.state.lock();
+        state.record_arrival(now);
+        state.update_interval(&self.config);
+        state.entries.push_back(entry.clone());
+        state.arrivals.push_back(now);
+        self.export_due(&mut state);
+    }
+
+    fn next_evidence_ts(&self) -> u64 {
+        // See JsonlSink::next_evidence_ts for why this wraps.
+        self.timestamp_seq
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Evidence emission helpers
 // ---------------------------------------------------------------------------
@@ -302,6 +841,7 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::time::VirtualClock;
     use franken_evidence::EvidenceLedgerBuilder;
     use std::sync::Arc;
 
@@ -343,6 +883,214 @@ mod tests {
         sink.emit(&test_entry("budget"));
     }
 
+    #[test]
+    fn ring_buffer_sink_batches_until_full_or_flushed() {
+        let collector = Arc::new(CollectorSink::new());
+        let sink = RingBufferSink::new(collector.clone(), 3);
+        sink.emit(&test_entry("a"));
+        sink.emit(&test_entry("b"));
+        assert_eq!(sink.buffered(), 2);
+        assert!(collector.is_empty());
+
+        sink.emit(&test_entry("c"));
+        assert_eq!(sink.buffered(), 0, "full ring drains");
+        assert_eq!(collector.len(), 3);
+
+        sink.emit(&test_entry("d"));
+        assert_eq!(sink.try_flush_for(Duration::from_millis(10)), Some(1));
+        assert_eq!(sink.flush(), 0);
+        let components: Vec<String> =
+            collector.entries().into_iter().map(|entry| entry.component).collect();
+        assert_eq!(components, ["a", "b", "c", "d"]);
+    }
+
+    #[derive(Debug)]
+    struct ScriptedExporter {
+        clock: Arc<VirtualClock>,
+        latency: Mutex<Duration>,
+        failures: Mutex<u32>,
+        exported: Mutex<Vec<String>>,
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl ScriptedExporter {
+        fn new(clock: &Arc<VirtualClock>, latency: Duration) -> Arc<Self> {
+            Arc::new(Self {
+                clock: Arc::clone(clock),
+                latency: Mutex::new(latency),
+                failures: Mutex::new(0),
+                exported: Mutex::new(Vec::new()),
+                batches: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn set_latency(&self, latency: Duration) {
+            *self.latency.lock() = latency;
+        }
+
+        fn fail_next(&self, count: u32) {
+            *self.failures.lock() = count;
+        }
+    }
+
+    impl BatchExporter for ScriptedExporter {
+        fn export(&self, batch: &[EvidenceLedger]) -> std::io::Result<()> {
+            self.clock.advance(self.latency.lock().as_nanos() as u64);
+            let mut failures = self.failures.lock();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(std::io::Error::other("scripted export failure"));
+            }
+            self.batches.lock().push(batch.len());
+            let components = batch.iter().map(|entry| entry.component.clone());
+            self.exported.lock().extend(components);
+            Ok(())
+        }
+    }
+
+    fn adaptive_sink(
+        exporter: &Arc<ScriptedExporter>,
+        clock: &Arc<VirtualClock>,
+        config: AdaptiveBatchConfig,
+    ) -> AdaptiveBatchSink {
+        AdaptiveBatchSink::new(exporter.clone(), config, BatchGrowth::default())
+            .with_time_source(clock.clone())
+    }
+
+    /// Emits `count` entries named `e{start}..`, `gap` apart.
+    fn emit_spaced(
+        sink: &AdaptiveBatchSink,
+        clock: &VirtualClock,
+        start: usize,
+        count: usize,
+        gap: Duration,
+    ) {
+        for index in start..start + count {
+            clock.advance(gap.as_nanos() as u64);
+            sink.emit(&test_entry(&format!("e{index}")));
+        }
+    }
+
+    fn fast_downstream_batches() -> (Vec<usize>, AdaptiveBatchMetrics) {
+        let clock = Arc::new(VirtualClock::new());
+        let exporter = ScriptedExporter::new(&clock, Duration::from_millis(1));
+        let config = AdaptiveBatchConfig::default()
+            .initial_batch_size(4)
+            .batch_bounds(2, 64);
+        let sink = adaptive_sink(&exporter, &clock, config);
+        emit_spaced(&sink, &clock, 0, 500, Duration::from_micros(100));
+        let batches = exporter.batches.lock().clone();
+        (batches, sink.metrics())
+    }
+
+    #[test]
+    fn adaptive_batch_grows_on_fast_downstream() {
+        let (batches, metrics) = fast_downstream_batches();
+        assert_eq!(&batches[..5], [4, 8, 16, 32, 64]);
+        assert!(batches.iter().all(|&size| size <= 64), "{batches:?}");
+        assert_eq!(metrics.batch_size, 64, "growth stops at the bound");
+        assert_eq!(metrics.increases, 4);
+        assert_eq!(metrics.decreases, 0);
+        assert_eq!(metrics.last_latency, Some(Duration::from_millis(1)));
+
+        let (replayed, _) = fast_downstream_batches();
+        assert_eq!(batches, replayed, "scripted latencies replay identically");
+    }
+
+    #[test]
+    fn adaptive_batch_shrinks_and_shortens_interval_on_slow_downstream() {
+        let clock = Arc::new(VirtualClock::new());
+        let exporter = ScriptedExporter::new(&clock, Duration::from_millis(200));
+        let config = AdaptiveBatchConfig::default()
+            .initial_batch_size(16)
+            .batch_bounds(2, 64);
+        let sink = adaptive_sink(&exporter, &clock, config);
+
+        emit_spaced(&sink, &clock, 0, 15, Duration::from_millis(1));
+        let before = sink.metrics();
+        assert_eq!(before.flush_interval, Duration::from_millis(16));
+
+        emit_spaced(&sink, &clock, 15, 1, Duration::from_millis(1));
+        let after = sink.metrics();
+        assert_eq!(after.batch_size, 8, "slow export halves the batch");
+        assert_eq!(after.last_latency, Some(Duration::from_millis(200)));
+        assert_eq!(after.flush_interval, Duration::from_millis(10));
+        assert!(after.flush_interval < before.flush_interval);
+
+        emit_spaced(&sink, &clock, 16, 100, Duration::from_millis(1));
+        let metrics = sink.metrics();
+        assert_eq!(metrics.batch_size, 2, "shrinking stops at the bound");
+        assert_eq!(metrics.increases, 0);
+        assert_eq!(metrics.decreases, 3);
+    }
+
+    #[test]
+    fn adaptive_batch_flushes_promptly_at_low_arrival_rate() {
+        let clock = Arc::new(VirtualClock::new());
+        let exporter = ScriptedExporter::new(&clock, Duration::from_millis(1));
+        let sink = adaptive_sink(&exporter, &clock, AdaptiveBatchConfig::default());
+
+        emit_spaced(&sink, &clock, 0, 2, Duration::from_millis(1));
+        assert_eq!(
+            sink.metrics().flush_interval,
+            Duration::from_millis(64),
+            "at 1 kHz a 64-entry batch fills in 64ms"
+        );
+        assert_eq!(sink.tick(), 0);
+        clock.advance(Duration::from_millis(63).as_nanos() as u64);
+        assert_eq!(sink.tick(), 2);
+
+        let mut previous_wait = Duration::from_millis(64);
+        for index in 2..8 {
+            clock.advance(Duration::from_secs(5).as_nanos() as u64);
+            let arrived = clock.now();
+            sink.emit(&test_entry(&format!("e{index}")));
+            let deadline = sink.next_flush_at().expect("entry buffered");
+            let wait = Duration::from_nanos(deadline.duration_since(arrived));
+            assert!(wait <= previous_wait, "entry {index}: slower arrivals wait less");
+            previous_wait = wait;
+            assert_eq!(sink.tick(), 0, "not due yet");
+            clock.advance_to(deadline);
+            assert_eq!(sink.tick(), 1);
+        }
+        assert_eq!(previous_wait, Duration::from_millis(10), "settles at min_interval");
+        assert_eq!(exporter.exported.lock().len(), 8);
+    }
+
+    #[test]
+    fn adaptive_batch_keeps_order_across_failures_and_adaptation() {
+        let clock = Arc::new(VirtualClock::new());
+        let exporter = ScriptedExporter::new(&clock, Duration::from_millis(1));
+        let config = AdaptiveBatchConfig::default()
+            .initial_batch_size(8)
+            .batch_bounds(2, 32);
+        let sink = adaptive_sink(&exporter, &clock, config);
+
+        let mut emitted = 0;
+        for round in 0..20 {
+            let latency = if round % 3 == 0 { 120 } else { 1 };
+            exporter.set_latency(Duration::from_millis(latency));
+            if round % 4 == 1 {
+                exporter.fail_next(2);
+            }
+            emit_spaced(&sink, &clock, emitted, 25, Duration::from_millis(2));
+            emitted += 25;
+            let size = sink.metrics().batch_size;
+            assert!((2..=32).contains(&size), "round {round}: size {size}");
+        }
+        *exporter.failures.lock() = 0;
+        sink.flush();
+
+        let metrics = sink.metrics();
+        assert!(metrics.failed_exports > 0 && metrics.increases > 0 && metrics.decreases > 0);
+        assert_eq!(metrics.buffered, 0);
+        assert_eq!(metrics.entries_exported, emitted as u64);
+        let expected: Vec<String> = (0..emitted).map(|index| format!("e{index}")).collect();
+        assert_eq!(*exporter.exported.lock(), expected, "no loss, no reordering");
+        let batches = exporter.batches.lock();
+        assert!(batches.iter().all(|&size| size <= 32), "{batches:?}");
+    }
+
     #[test]
     fn jsonl_sink_write_and_read() {
         let dir = tempfile::tempdir().unwrap();
@@ -358,6 +1106,36 @@ mod tests {
         assert_eq!(entries[1].component, "cancel");
     }
 
+    #[test]
+    fn jsonl_sink_indexed_sidecar_tracks_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence.jsonl");
+        JsonlSink::open(path.clone())
+            .unwrap()
+            .emit(&test_entry("existing"));
+
+        let sink = JsonlSink::open_indexed(path.clone()).unwrap();
+        sink.emit(&test_entry("scheduler"));
+        sink.sync_index().unwrap();
+
+        let index = EvidenceIndex::load_sidecar(&path)
+            .unwrap()
+            .expect("sidecar");
+        assert_eq!(index.entries, 2);
+        assert_eq!(index.indexed_bytes, std::fs::metadata(&path).unwrap().len());
+        assert!(index.components.may_contain("existing"));
+        assert!(index.components.may_contain("scheduler"));
+
+        let skipped = crate::evidence::Query::new()
+            .component("budget")
+            .execute([&path])
+            .unwrap();
+        assert_eq!(
+            skipped.summary.files_skipped,
+            vec![path.display().to_string()]
+        );
+    }
+
     // ---- emit helpers ----
 
     #[test]
@@ -13,6 +13,7 @@
 
 #![allow(clippy::unused_async)]
 
+use crate::error::map_fd_exhaustion;
 use crate::fs::OpenOptions;
 use crate::fs::metadata::{Metadata, Permissions};
 use crate::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
@@ -219,8 +220,11 @@ impl File {
     ///
     /// See [`OpenOptions::open`] for more options.
     pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Fs)?;
         let path = path.as_ref().to_owned();
-        let file = spawn_blocking_io(move || std::fs::File::open(&path)).await?;
+        let file = spawn_blocking_io(move || std::fs::File::open(&path))
+            .await
+            .map_err(map_fd_exhaustion)?;
         Ok(Self::from_std(file))
     }
 
@@ -229,8 +233,11 @@ impl File {
     /// This function will create a file if it does not exist, and will truncate it if it does.
     /// A started open may create or truncate the path after cancellation.
     pub async fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Fs)?;
         let path = path.as_ref().to_owned();
-        let file = spawn_blocking_io(move || std::fs::File::create(&path)).await?;
+        let file = spawn_blocking_io(move || std::fs::File::create(&path))
+            .await
+            .map_err(map_fd_exhaustion)?;
         Ok(Self::from_std(file))
     }
 
@@ -240,6 +247,7 @@ impl File {
     /// creators. If this succeeds, the returned file is guaranteed to be new.
     /// A started creation may still commit after the future is dropped.
     pub async fn create_new(path: impl AsRef<Path>) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Fs)?;
         let path = path.as_ref().to_owned();
         let file = spawn_blocking_io(move || {
             std::fs::OpenOptions::new()
@@ -248,7 +256,8 @@ impl File {
                 .create_new(true)
                 .open(&path)
         })
-        .await?;
+        .await
+        .map_err(map_fd_exhaustion)?;
         Ok(Self::from_std(file))
     }
 
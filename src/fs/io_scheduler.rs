@@ -0,0 +1,1484 @@
+//! Per-device I/O admission scheduling for filesystem operations.
+//!
+//! An [`IoScheduler`] bounds how much filesystem work runs concurrently
+//! against each storage device. Paths are mapped to named devices by
+//! longest path prefix ([`IoSchedulerConfig::route`]); each device has a
+//! weighted capacity shared by every operation class plus a per-class
+//! in-flight limit ([`DeviceLimits`]). Paths that match no route are not
+//! scheduled.
+//!
+//! Once installed ([`IoScheduler::install`]), the path-level operations in
+//! [`crate::fs`] acquire permits automatically: [`read`](crate::fs::read) and
+//! [`read_to_string`](crate::fs::read_to_string) as [`IoClass::Read`],
+//! [`write`](crate::fs::write) as [`IoClass::Write`],
+//! [`copy`](crate::fs::copy) as a read of the source plus a write of the
+//! destination, and [`stage_write_atomic`](crate::fs::stage_write_atomic) as a
+//! write plus an fsync. Code working with raw file handles acquires permits
+//! itself through [`IoScheduler::acquire`]. [`ScheduledVfs`] applies the same
+//! admission to any [`Vfs`], including the lab's `SimFs`.
+//!
+//! # Fairness and the priority lane
+//!
+//! Waiters are granted in arrival order per device class, so a large
+//! request is not starved by a stream of small ones. A request marked
+//! [`IoPriority::Critical`] (a WAL fsync, say) is considered before every
+//! normal waiter and may use the device's
+//! [`priority_reserve`](DeviceLimits::priority_reserve), which normal
+//! requests never touch, so it is not queued behind bulk reads.
+//!
+//! A request that needs permits from several classes or devices takes all
+//! of them at once or none, so two such requests cannot deadlock by each
+//! holding half of what the other needs.
+//!
+//! # Cancel Safety
+//!
+//! Dropping an [`IoAcquire`] withdraws the request; permits it was granted
+//! but never returned go straight back. The automatic permits are held by
+//! the blocking work itself, so a soft-cancelled operation keeps its permit
+//! until the syscall actually finishes.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::fs::io_scheduler::{
+//!     ClassLimits, DeviceLimits, IoClass, IoRequest, IoScheduler, IoSchedulerConfig,
+//! };
+//!
+//! let scheduler = IoScheduler::new(
+//!     IoSchedulerConfig::new()
+//!         .device(
+//!             "hdd0",
+//!             DeviceLimits::new(16)
+//!                 .class(IoClass::Read, ClassLimits::new(8, 1))
+//!                 .priority_reserve(2),
+//!         )
+//!         .route("/var/lib/app", "hdd0"),
+//! );
+//! scheduler.install();
+//!
+//! // WAL fsync on a raw handle: skip the bulk queue.
+//! let _permit = scheduler.acquire(IoRequest::fsync("/var/lib/app/wal").critical()).await;
+//! wal.sync_data().await?;
+//! ```
+
+use crate::fs::metadata::{Metadata, Permissions};
+use crate::fs::open_options::OpenOptions;
+use crate::fs::read_dir::ReadDir;
+use crate::fs::vfs::Vfs;
+use crate::observability::metrics::{Histogram, HistogramSnapshot};
+use crate::time::{TimeSource, WallClock};
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the wait-time histogram buckets.
+const WAIT_BUCKETS_SECS: [f64; 10] = [
+    0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+];
+
+/// Upper bounds of the queue-depth histogram buckets.
+const QUEUE_DEPTH_BUCKETS: [f64; 9] = [0.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 256.0];
+
+/// The scheduler installed for the automatic permits in [`crate::fs`].
+static INSTALLED: RwLock<Option<IoScheduler>> = RwLock::new(None);
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Class of a filesystem operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IoClass {
+    /// Reading file data.
+    Read,
+    /// Writing file data.
+    Write,
+    /// `sync_all` / `sync_data`.
+    Fsync,
+}
+
+impl IoClass {
+    /// Every class, in snapshot order.
+    pub const ALL: [Self; 3] = [Self::Read, Self::Write, Self::Fsync];
+
+    /// Returns a stable lowercase name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Fsync => "fsync",
+        }
+    }
+
+    const fn index(self) -> usize {
+        match self {
+            Self::Read => 0,
+            Self::Write => 1,
+            Self::Fsync => 2,
+        }
+    }
+}
+
+/// Admission priority of a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IoPriority {
+    /// Critical requests are granted before every normal waiter and may use
+    /// the device's priority reserve.
+    Critical,
+    /// Bulk work, granted in arrival order.
+    #[default]
+    Normal,
+}
+
+/// Limits for one operation class on a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassLimits {
+    /// Operations of this class that may run at once (at least 1).
+    pub max_in_flight: usize,
+    /// Device capacity each operation of this class consumes (at least 1,
+    /// at most the device capacity).
+    pub weight: usize,
+}
+
+impl ClassLimits {
+    /// Creates class limits.
+    #[must_use]
+    pub const fn new(max_in_flight: usize, weight: usize) -> Self {
+        Self {
+            max_in_flight,
+            weight,
+        }
+    }
+}
+
+/// Limits for one device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceLimits {
+    /// Total weight of operations that may run at once (at least 1).
+    pub capacity: usize,
+    /// Capacity only [`IoPriority::Critical`] requests may use. Always
+    /// leaves at least one unit for normal requests.
+    pub priority_reserve: usize,
+    classes: [ClassLimits; 3],
+}
+
+impl DeviceLimits {
+    /// Creates limits with `capacity` shared by all classes, each class
+    /// weighing 1 and limited only by the capacity, and no priority reserve.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            priority_reserve: 0,
+            classes: [ClassLimits::new(capacity, 1); 3],
+        }
+    }
+
+    /// Sets the limits for `class`.
+    #[must_use]
+    pub fn class(mut self, class: IoClass, limits: ClassLimits) -> Self {
+        self.classes[class.index()] = limits;
+        self
+    }
+
+    /// Reserves `reserve` units of capacity for critical requests.
+    #[must_use]
+    pub fn priority_reserve(mut self, reserve: usize) -> Self {
+        self.priority_reserve = reserve;
+        self
+    }
+
+    /// Returns the limits for `class`.
+    #[must_use]
+    pub const fn limits(&self, class: IoClass) -> ClassLimits {
+        self.classes[class.index()]
+    }
+
+    fn max_in_flight(&self, class: IoClass) -> usize {
+        self.limits(class).max_in_flight.max(1)
+    }
+
+    fn weight(&self, class: IoClass) -> usize {
+        self.limits(class).weight.clamp(1, self.capacity.max(1))
+    }
+
+    fn usable(&self, priority: IoPriority) -> usize {
+        let capacity = self.capacity.max(1);
+        match priority {
+            IoPriority::Critical => capacity,
+            IoPriority::Normal => capacity - self.priority_reserve.min(capacity - 1),
+        }
+    }
+}
+
+/// Configuration for an [`IoScheduler`].
+#[derive(Clone)]
+pub struct IoSchedulerConfig {
+    devices: BTreeMap<String, DeviceLimits>,
+    routes: Vec<(PathBuf, String)>,
+    clock: Arc<dyn TimeSource>,
+}
+
+impl std::fmt::Debug for IoSchedulerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IoSchedulerConfig")
+            .field("devices", &self.devices)
+            .field("routes", &self.routes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for IoSchedulerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IoSchedulerConfig {
+    /// Creates a configuration with no devices; nothing is scheduled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            devices: BTreeMap::new(),
+            routes: Vec::new(),
+            clock: Arc::new(WallClock::new()),
+        }
+    }
+
+    /// Declares device `name` with `limits`.
+    #[must_use]
+    pub fn device(mut self, name: impl Into<String>, limits: DeviceLimits) -> Self {
+        self.devices.insert(name.into(), limits);
+        self
+    }
+
+    /// Routes paths under `prefix` to device `name`. The longest matching
+    /// prefix wins.
+    #[must_use]
+    pub fn route(mut self, prefix: impl Into<PathBuf>, name: impl Into<String>) -> Self {
+        insert_route(&mut self.routes, prefix.into(), name.into());
+        self
+    }
+
+    /// Measures wait times on `clock` instead of the wall clock.
+    #[must_use]
+    pub fn clock(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+/// Inserts or replaces a route, keeping routes ordered longest prefix first.
+fn insert_route(routes: &mut Vec<(PathBuf, String)>, prefix: PathBuf, device: String) {
+    routes.retain(|(existing, _)| *existing != prefix);
+    routes.push((prefix, device));
+    routes.sort_by(|(a, _), (b, _)| {
+        let depth = |path: &Path| path.components().count();
+        depth(b).cmp(&depth(a)).then_with(|| a.cmp(b))
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Requests and permits
+// ---------------------------------------------------------------------------
+
+/// The operations one [`IoScheduler::acquire`] call covers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoRequest {
+    ops: Vec<(PathBuf, IoClass)>,
+    priority: IoPriority,
+}
+
+impl IoRequest {
+    /// Creates an empty request.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A read of `path`.
+    #[must_use]
+    pub fn read(path: impl Into<PathBuf>) -> Self {
+        Self::new().with(path, IoClass::Read)
+    }
+
+    /// A write of `path`.
+    #[must_use]
+    pub fn write(path: impl Into<PathBuf>) -> Self {
+        Self::new().with(path, IoClass::Write)
+    }
+
+    /// An fsync of `path`.
+    #[must_use]
+    pub fn fsync(path: impl Into<PathBuf>) -> Self {
+        Self::new().with(path, IoClass::Fsync)
+    }
+
+    /// Adds an operation of `class` on `path`.
+    #[must_use]
+    pub fn with(mut self, path: impl Into<PathBuf>, class: IoClass) -> Self {
+        self.ops.push((path.into(), class));
+        self
+    }
+
+    /// Marks the request critical.
+    #[must_use]
+    pub fn critical(mut self) -> Self {
+        self.priority = IoPriority::Critical;
+        self
+    }
+
+    /// Returns the request's priority.
+    #[must_use]
+    pub const fn priority(&self) -> IoPriority {
+        self.priority
+    }
+}
+
+/// One (device, class) slot of a request, with the weight it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Claim {
+    device: String,
+    class: IoClass,
+    weight: usize,
+}
+
+/// Permits held for the duration of an operation; released on drop.
+#[derive(Debug)]
+#[must_use = "permits are released as soon as they are dropped"]
+pub struct IoPermit {
+    inner: Option<Arc<Inner>>,
+    claims: Vec<Claim>,
+}
+
+impl IoPermit {
+    const fn unscheduled() -> Self {
+        Self {
+            inner: None,
+            claims: Vec::new(),
+        }
+    }
+
+    /// Returns the (device, class) slots this permit holds. Empty when no
+    /// path of the request was routed to a device.
+    pub fn slots(&self) -> impl Iterator<Item = (&str, IoClass)> {
+        self.claims
+            .iter()
+            .map(|claim| (claim.device.as_str(), claim.class))
+    }
+}
+
+impl Drop for IoPermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let claims = std::mem::take(&mut self.claims);
+            inner.release(&claims);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scheduler state
+// ---------------------------------------------------------------------------
+
+/// Waiters are ordered by lane (critical first), then arrival.
+type WaiterKey = (IoPriority, u64);
+
+#[derive(Debug)]
+struct Waiter {
+    claims: Vec<Claim>,
+    enqueued_at: Time,
+    waker: Option<Waker>,
+    granted: bool,
+}
+
+#[derive(Debug)]
+struct ClassState {
+    in_flight: usize,
+    queued: usize,
+    granted: u64,
+    peak_in_flight: usize,
+    queue_depth: Histogram,
+    wait_time: Histogram,
+}
+
+impl ClassState {
+    fn new() -> Self {
+        Self {
+            in_flight: 0,
+            queued: 0,
+            granted: 0,
+            peak_in_flight: 0,
+            queue_depth: Histogram::new("fs_io_queue_depth", QUEUE_DEPTH_BUCKETS.to_vec()),
+            wait_time: Histogram::new("fs_io_wait_seconds", WAIT_BUCKETS_SECS.to_vec()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DeviceState {
+    limits: DeviceLimits,
+    used: usize,
+    classes: [ClassState; 3],
+}
+
+impl DeviceState {
+    fn new(limits: DeviceLimits) -> Self {
+        Self {
+            limits,
+            used: 0,
+            classes: std::array::from_fn(|_| ClassState::new()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    devices: BTreeMap<String, DeviceState>,
+    routes: Vec<(PathBuf, String)>,
+    waiters: BTreeMap<WaiterKey, Waiter>,
+    next_seq: u64,
+}
+
+impl State {
+    fn device_for(&self, path: &Path) -> Option<&str> {
+        self.routes
+            .iter()
+            .find(|(prefix, device)| path.starts_with(prefix) && self.devices.contains_key(device))
+            .map(|(_, device)| device.as_str())
+    }
+
+    /// Maps a request to its distinct (device, class) slots.
+    fn resolve(&self, request: &IoRequest) -> Vec<Claim> {
+        let slots: BTreeSet<(&str, IoClass)> = request
+            .ops
+            .iter()
+            .filter_map(|(path, class)| Some((self.device_for(path)?, *class)))
+            .collect();
+        slots
+            .into_iter()
+            .map(|(device, class)| Claim {
+                device: device.to_string(),
+                class,
+                weight: 0,
+            })
+            .collect()
+    }
+
+    /// Returns true if a waiter would be served before a new request for
+    /// `claims`: one queued for the same slot, or a critical one on the same
+    /// device when the request is normal.
+    fn has_waiters_ahead(&self, claims: &[Claim], priority: IoPriority) -> bool {
+        self.waiters.iter().any(|((lane, _), waiter)| {
+            !waiter.granted
+                && waiter.claims.iter().any(|queued| {
+                    claims.iter().any(|claim| {
+                        claim.device == queued.device
+                            && (claim.class == queued.class
+                                || (*lane == IoPriority::Critical
+                                    && priority == IoPriority::Normal))
+                    })
+                })
+        })
+    }
+
+    /// Takes every slot of `claims` if all of them are available.
+    fn try_take(&mut self, claims: &mut [Claim], priority: IoPriority) -> bool {
+        let mut used: BTreeMap<&str, usize> = BTreeMap::new();
+        for claim in claims.iter() {
+            let device = &self.devices[&claim.device];
+            let class = &device.classes[claim.class.index()];
+            if class.in_flight >= device.limits.max_in_flight(claim.class) {
+                return false;
+            }
+            let total = used.entry(&claim.device).or_insert(device.used);
+            *total += device.limits.weight(claim.class);
+            if *total > device.limits.usable(priority) {
+                return false;
+            }
+        }
+        for claim in claims.iter_mut() {
+            let device = self
+                .devices
+                .get_mut(&claim.device)
+                .expect("resolved device");
+            claim.weight = device.limits.weight(claim.class);
+            device.used += claim.weight;
+            let class = &mut device.classes[claim.class.index()];
+            class.in_flight += 1;
+            class.granted += 1;
+            class.peak_in_flight = class.peak_in_flight.max(class.in_flight);
+        }
+        true
+    }
+
+    fn give_back(&mut self, claims: &[Claim]) {
+        for claim in claims {
+            if let Some(device) = self.devices.get_mut(&claim.device) {
+                device.used = device.used.saturating_sub(claim.weight);
+                let class = &mut device.classes[claim.class.index()];
+                class.in_flight = class.in_flight.saturating_sub(1);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn set_queued(&mut self, claims: &[Claim], enqueue: bool) {
+        for claim in claims {
+            let device = self
+                .devices
+                .get_mut(&claim.device)
+                .expect("resolved device");
+            let class = &mut device.classes[claim.class.index()];
+            if enqueue {
+                class.queue_depth.observe(class.queued as f64);
+                class.queued += 1;
+            } else {
+                class.queued = class.queued.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Grants every waiter that fits, in order, and returns their wakers.
+    ///
+    /// A waiter that does not fit blocks its (device, class) slots for the
+    /// normal waiters behind it; a critical waiter that does not fit blocks
+    /// its whole devices for them, so freed capacity goes to it first.
+    fn dispatch(&mut self, now: Time) -> Vec<Waker> {
+        let mut blocked_slots: BTreeSet<(String, IoClass)> = BTreeSet::new();
+        let mut blocked_devices: BTreeSet<String> = BTreeSet::new();
+        let mut wakers = Vec::new();
+        let keys: Vec<WaiterKey> = self.waiters.keys().copied().collect();
+        for key in keys {
+            let (priority, _) = key;
+            if self.waiters[&key].granted {
+                continue;
+            }
+            let mut waiter = self.waiters.remove(&key).expect("waiter");
+            let blocked = waiter.claims.iter().any(|claim| {
+                blocked_slots.contains(&(claim.device.clone(), claim.class))
+                    || (priority == IoPriority::Normal && blocked_devices.contains(&claim.device))
+            });
+            if !blocked && self.try_take(&mut waiter.claims, priority) {
+                self.set_queued(&waiter.claims, false);
+                let waited = Duration::from_nanos(now.duration_since(waiter.enqueued_at));
+                for claim in &waiter.claims {
+                    let class = &self.devices[&claim.device].classes[claim.class.index()];
+                    class.wait_time.observe(waited.as_secs_f64());
+                }
+                waiter.granted = true;
+                wakers.extend(waiter.waker.take());
+            } else {
+                for claim in &waiter.claims {
+                    blocked_slots.insert((claim.device.clone(), claim.class));
+                    if priority == IoPriority::Critical {
+                        blocked_devices.insert(claim.device.clone());
+                    }
+                }
+            }
+            self.waiters.insert(key, waiter);
+        }
+        wakers
+    }
+}
+
+struct Inner {
+    state: Mutex<State>,
+    clock: Arc<dyn TimeSource>,
+}
+
+impl std::fmt::Debug for Inner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Inner")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Inner {
+    /// Runs `f` on the state, then dispatches and wakes granted waiters.
+    fn update<R>(&self, f: impl FnOnce(&mut State) -> R) -> R {
+        let now = self.clock.now();
+        let mut state = self.state.lock();
+        let result = f(&mut state);
+        let wakers = state.dispatch(now);
+        drop(state);
+        for waker in wakers {
+            waker.wake();
+        }
+        result
+    }
+
+    fn release(&self, claims: &[Claim]) {
+        self.update(|state| state.give_back(claims));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// IoScheduler
+// ---------------------------------------------------------------------------
+
+/// Per-device weighted admission for filesystem operations.
+///
+/// Cloning shares the same scheduler.
+#[derive(Debug, Clone)]
+pub struct IoScheduler {
+    inner: Arc<Inner>,
+}
+
+impl IoScheduler {
+    /// Creates a scheduler from `config`.
+    #[must_use]
+    pub fn new(config: IoSchedulerConfig) -> Self {
+        let devices = config
+            .devices
+            .into_iter()
+            .map(|(name, limits)| (name, DeviceState::new(limits)))
+            .collect();
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    devices,
+                    routes: config.routes,
+                    waiters: BTreeMap::new(),
+                    next_seq: 0,
+                }),
+                clock: config.clock,
+            }),
+        }
+    }
+
+    /// Makes this scheduler admit the automatic permits of [`crate::fs`]
+    /// operations, replacing any previously installed scheduler.
+    pub fn install(&self) {
+        let mut installed = INSTALLED
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *installed = Some(self.clone());
+    }
+
+    /// Removes the installed scheduler; `fs` operations run unscheduled.
+    pub fn uninstall() {
+        let mut installed = INSTALLED
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *installed = None;
+    }
+
+    /// Returns the installed scheduler, if any.
+    #[must_use]
+    pub fn installed() -> Option<Self> {
+        INSTALLED
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Returns the device `path` is routed to.
+    #[must_use]
+    pub fn device_for(&self, path: &Path) -> Option<String> {
+        self.inner
+            .state
+            .lock()
+            .device_for(path)
+            .map(str::to_string)
+    }
+
+    /// Replaces the limits of device `name`, declaring it if new.
+    ///
+    /// Operations already running keep their permits; lowered limits take
+    /// effect as they finish, raised limits admit waiters immediately.
+    pub fn set_limits(&self, name: &str, limits: DeviceLimits) {
+        self.inner.update(|state| match state.devices.get_mut(name) {
+            Some(device) => device.limits = limits,
+            None => {
+                state
+                    .devices
+                    .insert(name.to_string(), DeviceState::new(limits));
+            }
+        });
+    }
+
+    /// Routes paths under `prefix` to device `name`, replacing any route
+    /// with the same prefix. Affects requests made after the call.
+    pub fn set_route(&self, prefix: impl Into<PathBuf>, name: impl Into<String>) {
+        let mut state = self.inner.state.lock();
+        insert_route(&mut state.routes, prefix.into(), name.into());
+    }
+
+    /// Waits until every operation of `request` may run.
+    ///
+    /// Operations on unrouted paths need no permit; if none of the paths
+    /// is routed, the returned permit is empty and ready immediately.
+    pub fn acquire(&self, request: IoRequest) -> IoAcquire {
+        IoAcquire {
+            inner: Arc::clone(&self.inner),
+            request: Some(request),
+            key: None,
+        }
+    }
+
+    /// Takes the permits for `request` if they are available now and no
+    /// earlier waiter is queued for them.
+    #[must_use]
+    pub fn try_acquire(&self, request: &IoRequest) -> Option<IoPermit> {
+        let mut state = self.inner.state.lock();
+        let mut claims = state.resolve(request);
+        if state.has_waiters_ahead(&claims, request.priority)
+            || !state.try_take(&mut claims, request.priority)
+        {
+            return None;
+        }
+        drop(state);
+        Some(IoPermit {
+            inner: Some(Arc::clone(&self.inner)),
+            claims,
+        })
+    }
+
+    /// Returns per-device, per-class counters and histograms.
+    #[must_use]
+    pub fn snapshot(&self) -> IoSchedulerSnapshot {
+        let state = self.inner.state.lock();
+        let devices = state
+            .devices
+            .iter()
+            .map(|(name, device)| IoDeviceSnapshot {
+                name: name.clone(),
+                limits: device.limits,
+                used: device.used,
+                classes: IoClass::ALL
+                    .iter()
+                    .map(|&class| {
+                        let stats = &device.classes[class.index()];
+                        IoClassSnapshot {
+                            class,
+                            in_flight: stats.in_flight,
+                            queued: stats.queued,
+                            granted: stats.granted,
+                            peak_in_flight: stats.peak_in_flight,
+                            queue_depth: stats.queue_depth.snapshot(),
+                            wait_time: stats.wait_time.snapshot(),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        drop(state);
+        IoSchedulerSnapshot { devices }
+    }
+}
+
+/// Acquires the installed scheduler's permits for `request`, if a scheduler
+/// is installed.
+pub(crate) async fn admit(request: IoRequest) -> Option<IoPermit> {
+    let scheduler = IoScheduler::installed()?;
+    Some(scheduler.acquire(request).await)
+}
+
+/// Future returned by [`IoScheduler::acquire`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct IoAcquire {
+    inner: Arc<Inner>,
+    request: Option<IoRequest>,
+    key: Option<WaiterKey>,
+}
+
+impl Future for IoAcquire {
+    type Output = IoPermit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoPermit> {
+        if let Some(request) = self.request.take() {
+            let now = self.inner.clock.now();
+            let key = self.inner.update(|state| {
+                let claims = state.resolve(&request);
+                if claims.is_empty() {
+                    return None;
+                }
+                state.set_queued(&claims, true);
+                let key = (request.priority, state.next_seq);
+                state.next_seq += 1;
+                state.waiters.insert(
+                    key,
+                    Waiter {
+                        claims,
+                        enqueued_at: now,
+                        waker: None,
+                        granted: false,
+                    },
+                );
+                Some(key)
+            });
+            let Some(key) = key else {
+                return Poll::Ready(IoPermit::unscheduled());
+            };
+            self.key = Some(key);
+        }
+        let key = self.key.expect("IoAcquire polled after completion");
+        let mut state = self.inner.state.lock();
+        let waiter = state.waiters.get_mut(&key).expect("queued waiter");
+        if waiter.granted {
+            let waiter = state.waiters.remove(&key).expect("queued waiter");
+            drop(state);
+            self.key = None;
+            return Poll::Ready(IoPermit {
+                inner: Some(Arc::clone(&self.inner)),
+                claims: waiter.claims,
+            });
+        }
+        match &mut waiter.waker {
+            Some(waker) => waker.clone_from(cx.waker()),
+            None => waiter.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for IoAcquire {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.inner.update(|state| {
+                if let Some(waiter) = state.waiters.remove(&key) {
+                    if waiter.granted {
+                        state.give_back(&waiter.claims);
+                    } else {
+                        state.set_queued(&waiter.claims, false);
+                    }
+                }
+            });
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Snapshot
+// ---------------------------------------------------------------------------
+
+/// Counters and histograms for one class on one device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoClassSnapshot {
+    /// The class.
+    pub class: IoClass,
+    /// Operations currently holding a permit.
+    pub in_flight: usize,
+    /// Requests waiting for a permit.
+    pub queued: usize,
+    /// Permits granted so far.
+    pub granted: u64,
+    /// Highest `in_flight` observed.
+    pub peak_in_flight: usize,
+    /// Queue depth seen by each arriving request.
+    pub queue_depth: HistogramSnapshot,
+    /// Time from arrival to grant, in seconds of the configured clock.
+    pub wait_time: HistogramSnapshot,
+}
+
+/// State of one device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoDeviceSnapshot {
+    /// Device name.
+    pub name: String,
+    /// Current limits.
+    pub limits: DeviceLimits,
+    /// Capacity held by running operations.
+    pub used: usize,
+    /// Per-class state, in [`IoClass::ALL`] order.
+    pub classes: Vec<IoClassSnapshot>,
+}
+
+impl IoDeviceSnapshot {
+    /// Returns the snapshot of `class`.
+    #[must_use]
+    pub fn class(&self, class: IoClass) -> &IoClassSnapshot {
+        &self.classes[class.index()]
+    }
+}
+
+/// Point-in-time view of an [`IoScheduler`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoSchedulerSnapshot {
+    /// Devices, sorted by name.
+    pub devices: Vec<IoDeviceSnapshot>,
+}
+
+impl IoSchedulerSnapshot {
+    /// Returns the snapshot of device `name`.
+    #[must_use]
+    pub fn device(&self, name: &str) -> Option<&IoDeviceSnapshot> {
+        self.devices.iter().find(|device| device.name == name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ScheduledVfs
+// ---------------------------------------------------------------------------
+
+/// A [`Vfs`] whose whole-file operations are admitted by an [`IoScheduler`].
+///
+/// `read`, `read_to_string`, `write`, and `copy` acquire permits like their
+/// [`crate::fs`] counterparts; other operations and open handles are passed
+/// through unscheduled.
+#[derive(Debug, Clone)]
+pub struct ScheduledVfs<V> {
+    inner: V,
+    scheduler: IoScheduler,
+}
+
+impl<V: Vfs> ScheduledVfs<V> {
+    /// Wraps `inner`.
+    #[must_use]
+    pub const fn new(inner: V, scheduler: IoScheduler) -> Self {
+        Self { inner, scheduler }
+    }
+
+    /// Returns the wrapped filesystem.
+    #[must_use]
+    pub const fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    /// Returns the scheduler.
+    #[must_use]
+    pub const fn scheduler(&self) -> &IoScheduler {
+        &self.scheduler
+    }
+}
+
+impl<V: Vfs> Vfs for ScheduledVfs<V> {
+    type File = V::File;
+
+    async fn open(&self, path: &Path, opts: &OpenOptions) -> io::Result<Self::File> {
+        self.inner.open(path, opts).await
+    }
+
+    async fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.inner.metadata(path).await
+    }
+
+    async fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.inner.symlink_metadata(path).await
+    }
+
+    async fn set_permissions(&self, path: &Path, perm: Permissions) -> io::Result<()> {
+        self.inner.set_permissions(path, perm).await
+    }
+
+    async fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        self.inner.remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.inner.remove_dir_all(path).await
+    }
+
+    async fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
+        self.inner.read_dir(path).await
+    }
+
+    async fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.remove_file(path).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy(&self, src: &Path, dst: &Path) -> io::Result<u64> {
+        let request = IoRequest::read(src).with(dst, IoClass::Write);
+        let _permit = self.scheduler.acquire(request).await;
+        self.inner.copy(src, dst).await
+    }
+
+    async fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        self.inner.hard_link(original, link).await
+    }
+
+    async fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.canonicalize(path).await
+    }
+
+    async fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.read_link(path).await
+    }
+
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let _permit = self.scheduler.acquire(IoRequest::read(path)).await;
+        self.inner.read(path).await
+    }
+
+    async fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let _permit = self.scheduler.acquire(IoRequest::read(path)).await;
+        self.inner.read_to_string(path).await
+    }
+
+    async fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let _permit = self.scheduler.acquire(IoRequest::write(path)).await;
+        self.inner.write(path, contents).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::network::LatencyModel;
+    use crate::lab::run_async_under_lab;
+    use crate::lab::sim_fs::{SimFs, SimFsConfig, SimFsOp, SimFsRule};
+    use crate::runtime::yield_now;
+    use crate::time::VirtualClock;
+    use crate::util::DetRng;
+    use futures_lite::future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn scheduler(limits: DeviceLimits) -> IoScheduler {
+        IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("d0", limits)
+                .route("/d0", "d0"),
+        )
+    }
+
+    fn class_stats(scheduler: &IoScheduler, class: IoClass) -> IoClassSnapshot {
+        let snapshot = scheduler.snapshot();
+        snapshot.device("d0").expect("device").class(class).clone()
+    }
+
+    /// Yields until `cond` holds, bounded so a broken scheduler fails the
+    /// test instead of hanging it.
+    async fn yield_until(mut cond: impl FnMut() -> bool) {
+        for _ in 0..1000 {
+            if cond() {
+                return;
+            }
+            yield_now().await;
+        }
+        panic!("condition never held");
+    }
+
+    #[test]
+    fn routes_pick_the_longest_prefix() {
+        init_test("routes_pick_the_longest_prefix");
+        let scheduler = IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("root", DeviceLimits::new(4))
+                .device("ssd", DeviceLimits::new(4))
+                .route("/data", "root")
+                .route("/data/fast", "ssd")
+                .route("/ghost", "missing"),
+        );
+        let routed = [
+            scheduler.device_for(Path::new("/data/fast/a")),
+            scheduler.device_for(Path::new("/data/slow/a")),
+            scheduler.device_for(Path::new("/ghost/a")),
+            scheduler.device_for(Path::new("/elsewhere")),
+        ];
+        let expected = [Some("ssd".into()), Some("root".into()), None, None];
+        crate::assert_with_log!(routed == expected, "routes", expected, routed);
+
+        let permit = scheduler.try_acquire(&IoRequest::read("/elsewhere/x"));
+        let slots = permit.as_ref().map(|permit| permit.slots().count());
+        crate::assert_with_log!(slots == Some(0), "unrouted is free", Some(0), slots);
+        crate::test_complete!("routes_pick_the_longest_prefix");
+    }
+
+    #[test]
+    fn concurrency_never_exceeds_class_limits() {
+        init_test("concurrency_never_exceeds_class_limits");
+        let limits = DeviceLimits::new(5)
+            .class(IoClass::Read, ClassLimits::new(3, 1))
+            .class(IoClass::Write, ClassLimits::new(2, 2))
+            .class(IoClass::Fsync, ClassLimits::new(1, 1));
+        for seed in 0..8 {
+            let scheduler = scheduler(limits);
+            let sched = scheduler.clone();
+            let (violations, _) = run_async_under_lab(seed, move |cx| async move {
+                let in_flight: Arc<[AtomicUsize; 3]> = Arc::new(Default::default());
+                let weight = Arc::new(AtomicUsize::new(0));
+                let violations = Arc::new(AtomicUsize::new(0));
+                let mut rng = DetRng::new(seed);
+                let mut handles = Vec::new();
+                for i in 0..30 {
+                    let class = IoClass::ALL[i % 3];
+                    let critical = i % 7 == 0;
+                    let yields = rng.next_u64() % 4;
+                    let (sched, in_flight) = (sched.clone(), Arc::clone(&in_flight));
+                    let (weight, violations) = (Arc::clone(&weight), Arc::clone(&violations));
+                    let handle = cx
+                        .spawn(move |_| async move {
+                            let mut request = IoRequest::new().with("/d0/file", class);
+                            if critical {
+                                request = request.critical();
+                            }
+                            let permit = sched.acquire(request).await;
+                            let running = in_flight[class.index()].fetch_add(1, Ordering::SeqCst);
+                            let cost = limits.weight(class);
+                            let used = weight.fetch_add(cost, Ordering::SeqCst) + cost;
+                            if running >= limits.max_in_flight(class) || used > limits.capacity {
+                                violations.fetch_add(1, Ordering::SeqCst);
+                            }
+                            for _ in 0..yields {
+                                yield_now().await;
+                            }
+                            in_flight[class.index()].fetch_sub(1, Ordering::SeqCst);
+                            weight.fetch_sub(cost, Ordering::SeqCst);
+                            drop(permit);
+                        })
+                        .expect("spawn");
+                    handles.push(handle);
+                }
+                for mut handle in handles {
+                    handle.join(&cx).await.expect("join");
+                }
+                violations.load(Ordering::SeqCst)
+            });
+            crate::assert_with_log!(violations == 0, "no violations", 0, violations);
+            let snapshot = scheduler.snapshot();
+            let device = snapshot.device("d0").expect("device");
+            for class in IoClass::ALL {
+                let stats = device.class(class);
+                let limit = limits.limits(class).max_in_flight;
+                crate::assert_with_log!(
+                    stats.peak_in_flight <= limit && stats.granted == 10,
+                    "peak within limit",
+                    limit,
+                    (stats.peak_in_flight, stats.granted)
+                );
+            }
+            crate::assert_with_log!(device.used == 0, "all released", 0, device.used);
+        }
+        crate::test_complete!("concurrency_never_exceeds_class_limits");
+    }
+
+    #[test]
+    fn critical_fsync_bypasses_bulk_queue() {
+        init_test("critical_fsync_bypasses_bulk_queue");
+        // Capacity 2 with 1 reserved: bulk work runs one at a time.
+        let scheduler = scheduler(DeviceLimits::new(2).priority_reserve(1));
+        let sched = scheduler.clone();
+        let (order, _) = run_async_under_lab(7, move |cx| async move {
+            let order = Arc::new(Mutex::new(Vec::new()));
+            let held = sched
+                .try_acquire(&IoRequest::read("/d0/bulk"))
+                .expect("idle");
+            let mut handles = Vec::new();
+            for name in ["read-a", "read-b"] {
+                let (sched, order) = (sched.clone(), Arc::clone(&order));
+                let handle = cx
+                    .spawn(move |_| async move {
+                        let _permit = sched.acquire(IoRequest::read("/d0/bulk")).await;
+                        order.lock().push(name);
+                    })
+                    .expect("spawn");
+                handles.push(handle);
+            }
+            yield_until(|| class_stats(&sched, IoClass::Read).queued == 2)
+                .await;
+
+            // The WAL sync goes straight through on the reserve.
+            let wal = sched.acquire(IoRequest::fsync("/d0/wal").critical()).await;
+            order.lock().push("wal");
+            drop(wal);
+
+            // A critical request that must wait is served before the queued
+            // bulk reads once capacity frees up.
+            let busy = sched
+                .try_acquire(&IoRequest::write("/d0/reserve").critical())
+                .expect("reserve");
+            let (wal_sched, wal_order) = (sched.clone(), Arc::clone(&order));
+            let wal_handle = cx
+                .spawn(move |_| async move {
+                    let _permit = wal_sched
+                        .acquire(IoRequest::fsync("/d0/wal").critical())
+                        .await;
+                    wal_order.lock().push("wal-2");
+                })
+                .expect("spawn");
+            handles.push(wal_handle);
+            yield_until(|| class_stats(&sched, IoClass::Fsync).queued == 1)
+                .await;
+            drop(busy);
+            drop(held);
+            for mut handle in handles {
+                handle.join(&cx).await.expect("join");
+            }
+            order.lock().clone()
+        });
+        let expected = ["wal", "wal-2", "read-a", "read-b"];
+        crate::assert_with_log!(order == expected, "order", expected, order);
+        crate::test_complete!("critical_fsync_bypasses_bulk_queue");
+    }
+
+    #[test]
+    fn reconfiguration_applies_mid_stream() {
+        init_test("reconfiguration_applies_mid_stream");
+        let scheduler = scheduler(DeviceLimits::new(1));
+        let sched = scheduler.clone();
+        let ((), _) = run_async_under_lab(9, move |cx| async move {
+            let held = sched.try_acquire(&IoRequest::read("/d0/a")).expect("idle");
+            let done = Arc::new(AtomicUsize::new(0));
+            let mut handles = Vec::new();
+            for _ in 0..3 {
+                let (sched, done) = (sched.clone(), Arc::clone(&done));
+                let handle = cx
+                    .spawn(move |_| async move {
+                        let permit = sched.acquire(IoRequest::read("/d0/a")).await;
+                        done.fetch_add(1, Ordering::SeqCst);
+                        permit
+                    })
+                    .expect("spawn");
+                handles.push(handle);
+            }
+            yield_until(|| class_stats(&sched, IoClass::Read).queued == 3)
+                .await;
+
+            // Raising the limit admits every waiter at once.
+            sched.set_limits("d0", DeviceLimits::new(4));
+            yield_until(|| done.load(Ordering::SeqCst) == 3).await;
+            let mut permits = Vec::new();
+            for mut handle in handles {
+                permits.push(handle.join(&cx).await.expect("join"));
+            }
+            let in_flight = class_stats(&sched, IoClass::Read).in_flight;
+            crate::assert_with_log!(in_flight == 4, "raised", 4, in_flight);
+
+            // Lowering it keeps running work; new work waits for room.
+            sched.set_limits("d0", DeviceLimits::new(2));
+            let in_flight = class_stats(&sched, IoClass::Read).in_flight;
+            crate::assert_with_log!(in_flight == 4, "kept", 4, in_flight);
+            let blocked = sched.try_acquire(&IoRequest::read("/d0/a")).is_none();
+            crate::assert_with_log!(blocked, "over new limit", true, blocked);
+            drop(held);
+            permits.truncate(1);
+            let admitted = sched.try_acquire(&IoRequest::read("/d0/a")).is_some();
+            crate::assert_with_log!(admitted, "room again", true, admitted);
+        });
+        crate::test_complete!("reconfiguration_applies_mid_stream");
+    }
+
+    #[test]
+    fn wait_time_metrics_follow_virtual_clock() {
+        init_test("wait_time_metrics_follow_virtual_clock");
+        let clock = Arc::new(VirtualClock::new());
+        let scheduler = IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("d0", DeviceLimits::new(1))
+                .route("/d0", "d0")
+                .clock(clock.clone()),
+        );
+        let (sched, tick) = (scheduler.clone(), Arc::clone(&clock));
+        let ((), _) = run_async_under_lab(3, move |cx| async move {
+            let held = sched.acquire(IoRequest::read("/d0/a")).await;
+            let mut handles = Vec::new();
+            for _ in 0..2 {
+                let sched = sched.clone();
+                let handle = cx
+                    .spawn(move |_| async move {
+                        let _permit = sched.acquire(IoRequest::read("/d0/a")).await;
+                        yield_now().await;
+                    })
+                    .expect("spawn");
+                handles.push(handle);
+                let queued = handles.len();
+                yield_until(|| class_stats(&sched, IoClass::Read).queued == queued).await;
+            }
+            tick.advance(5_000_000);
+            drop(held);
+            yield_until(|| class_stats(&sched, IoClass::Read).granted == 2)
+                .await;
+            tick.advance(20_000_000);
+            for mut handle in handles {
+                handle.join(&cx).await.expect("join");
+            }
+        });
+        let stats = class_stats(&scheduler, IoClass::Read);
+        // Waits: 0 (immediate), 5ms, and 25ms.
+        let total_ms = (stats.wait_time.sum * 1000.0).round();
+        crate::assert_with_log!(stats.wait_time.count == 3, "waits", 3, stats.wait_time.count);
+        crate::assert_with_log!(total_ms == 30.0, "total wait ms", 30.0, total_ms);
+        let expected = vec![1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0];
+        crate::assert_with_log!(
+            stats.wait_time.bucket_counts == expected,
+            "wait buckets",
+            expected,
+            stats.wait_time.bucket_counts
+        );
+        // Arrivals saw queue depths 0, 0, and 1.
+        let depths = stats.queue_depth.bucket_counts[..2].to_vec();
+        crate::assert_with_log!(depths == [2, 1], "queue depth", "[2, 1]", depths);
+        crate::test_complete!("wait_time_metrics_follow_virtual_clock");
+    }
+
+    #[test]
+    fn multi_class_requests_do_not_deadlock() {
+        init_test("multi_class_requests_do_not_deadlock");
+        let limits = DeviceLimits::new(2)
+            .class(IoClass::Read, ClassLimits::new(1, 1))
+            .class(IoClass::Write, ClassLimits::new(1, 1));
+        let scheduler = IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("d0", limits)
+                .device("d1", limits)
+                .route("/d0", "d0")
+                .route("/d1", "d1"),
+        );
+        for seed in 0..4 {
+            let sched = scheduler.clone();
+            let (completed, _) = run_async_under_lab(seed, move |cx| async move {
+                let completed = Arc::new(AtomicUsize::new(0));
+                let mut handles = Vec::new();
+                for i in 0..24 {
+                    let request = match i % 4 {
+                        0 => IoRequest::read("/d0/x").with("/d0/y", IoClass::Write),
+                        1 => IoRequest::write("/d0/y").with("/d0/x", IoClass::Read),
+                        2 => IoRequest::read("/d0/x").with("/d1/y", IoClass::Write),
+                        _ => IoRequest::read("/d1/y").with("/d0/x", IoClass::Write),
+                    };
+                    let (sched, completed) = (sched.clone(), Arc::clone(&completed));
+                    let handle = cx
+                        .spawn(move |_| async move {
+                            let permit = sched.acquire(request).await;
+                            yield_now().await;
+                            drop(permit);
+                            completed.fetch_add(1, Ordering::SeqCst);
+                        })
+                        .expect("spawn");
+                    handles.push(handle);
+                }
+                for mut handle in handles {
+                    handle.join(&cx).await.expect("join");
+                }
+                completed.load(Ordering::SeqCst)
+            });
+            crate::assert_with_log!(completed == 24, "all completed", 24, completed);
+        }
+        crate::test_complete!("multi_class_requests_do_not_deadlock");
+    }
+
+    #[test]
+    fn dropped_acquire_withdraws_its_request() {
+        init_test("dropped_acquire_withdraws_its_request");
+        let scheduler = scheduler(DeviceLimits::new(1));
+        let held = scheduler
+            .try_acquire(&IoRequest::read("/d0/a"))
+            .expect("idle");
+        let mut pending = scheduler.acquire(IoRequest::read("/d0/a"));
+        let first = future::block_on(future::poll_once(&mut pending));
+        crate::assert_with_log!(first.is_none(), "waits", true, first.is_none());
+        drop(pending);
+        drop(held);
+        let stats = class_stats(&scheduler, IoClass::Read);
+        crate::assert_with_log!(
+            stats.queued == 0 && stats.in_flight == 0,
+            "nothing left behind",
+            (0, 0),
+            (stats.queued, stats.in_flight)
+        );
+        crate::test_complete!("dropped_acquire_withdraws_its_request");
+    }
+
+    #[test]
+    fn scheduled_sim_fs_waits_in_disk_time() {
+        init_test("scheduled_sim_fs_waits_in_disk_time");
+        let slow = LatencyModel::Fixed(Duration::from_millis(4));
+        let sim = SimFs::new(
+            SimFsConfig::new(5)
+                .rule(SimFsRule::new("/other/**").latency(SimFsOp::Write, slow)),
+        );
+        let scheduler = IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("d0", DeviceLimits::new(1))
+                .route("/d0", "d0")
+                .clock(Arc::new(sim.clone())),
+        );
+        let vfs = ScheduledVfs::new(sim, scheduler.clone());
+        future::block_on(async {
+            for dir in ["/d0", "/other"] {
+                vfs.create_dir_all(Path::new(dir)).await.expect("mkdir");
+            }
+            let log = Path::new("/d0/log");
+            vfs.write(log, b"entry").await.expect("write");
+
+            let held = vfs.scheduler().try_acquire(&IoRequest::write("/d0/log"));
+            let mut read = Box::pin(vfs.read(log));
+            let waiting = future::poll_once(&mut read).await.is_none();
+            crate::assert_with_log!(waiting, "device saturated", true, waiting);
+
+            // Unscheduled work elsewhere advances the disk clock by 4ms.
+            let blob = Path::new("/other/blob");
+            vfs.write(blob, b"x").await.expect("write");
+            drop(held);
+            let contents = read.await.expect("read");
+            crate::assert_with_log!(contents == b"entry", "contents", "entry", contents);
+        });
+        let stats = class_stats(&scheduler, IoClass::Read);
+        let waited_ms = (stats.wait_time.sum * 1000.0).round();
+        crate::assert_with_log!(waited_ms == 4.0, "disk-time wait", 4.0, waited_ms);
+        crate::test_complete!("scheduled_sim_fs_waits_in_disk_time");
+    }
+
+    #[test]
+    fn installed_scheduler_admits_fs_reads() {
+        init_test("installed_scheduler_admits_fs_reads");
+        let name = format!("asupersync_io_sched_{}", std::process::id());
+        let dir = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&dir).expect("mkdir");
+        let file = dir.join("data");
+        std::fs::write(&file, b"hello").expect("seed file");
+        let scheduler = IoScheduler::new(
+            IoSchedulerConfig::new()
+                .device("tmp", DeviceLimits::new(1))
+                .route(&dir, "tmp"),
+        );
+        scheduler.install();
+
+        let held = scheduler
+            .try_acquire(&IoRequest::write(&file))
+            .expect("idle");
+        let mut read = Box::pin(crate::fs::read(file.clone()));
+        let waiting = future::block_on(future::poll_once(&mut read)).is_none();
+        let snapshot = scheduler.snapshot();
+        let queued = snapshot
+            .device("tmp")
+            .expect("device")
+            .class(IoClass::Read)
+            .queued;
+        drop(held);
+        let contents = future::block_on(read).expect("read");
+        IoScheduler::uninstall();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        crate::assert_with_log!(waiting && queued == 1, "fs::read queued", 1, queued);
+        crate::assert_with_log!(contents == b"hello", "contents", "hello", contents);
+        crate::test_complete!("installed_scheduler_admits_fs_reads");
+    }
+}
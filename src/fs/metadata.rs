@@ -9,45 +9,82 @@ use std::time::SystemTime;
 /// File metadata (mirrors `std::fs::Metadata`).
 #[derive(Debug, Clone)]
 pub struct Metadata {
-    pub(crate) inner: std::fs::Metadata,
+    pub(crate) inner: MetadataInner,
+}
+
+/// Backing store of a [`Metadata`]: the OS, or a simulated [`Vfs`](super::Vfs).
+#[derive(Debug, Clone)]
+pub(crate) enum MetadataInner {
+    Std(std::fs::Metadata),
+    Virtual(VirtualMetadata),
+}
+
+/// Metadata synthesized by a simulated [`Vfs`](super::Vfs) backend.
+#[derive(Debug, Clone)]
+pub(crate) struct VirtualMetadata {
+    pub(crate) kind: VirtualFileKind,
+    pub(crate) len: u64,
+    pub(crate) permissions: Permissions,
+    pub(crate) modified: SystemTime,
+    pub(crate) created: SystemTime,
+}
+
+/// File type of a simulated node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VirtualFileKind {
+    File,
+    Dir,
 }
 
 impl Metadata {
     /// Wraps a `std::fs::Metadata`.
     pub(crate) fn from_std(inner: std::fs::Metadata) -> Self {
-        Self { inner }
+        Self {
+            inner: MetadataInner::Std(inner),
+        }
+    }
+
+    /// Wraps metadata synthesized by a simulated backend.
+    pub(crate) fn from_virtual(inner: VirtualMetadata) -> Self {
+        Self {
+            inner: MetadataInner::Virtual(inner),
+        }
     }
 
     /// Returns the file type.
     #[must_use]
     pub fn file_type(&self) -> FileType {
-        FileType {
-            inner: self.inner.file_type(),
+        match &self.inner {
+            MetadataInner::Std(inner) => FileType::from_std(inner.file_type()),
+            MetadataInner::Virtual(inner) => FileType::from_virtual(inner.kind),
         }
     }
 
     /// Returns true if this metadata is for a directory.
     #[must_use]
     pub fn is_dir(&self) -> bool {
-        self.inner.is_dir()
+        self.file_type().is_dir()
     }
 
     /// Returns true if this metadata is for a regular file.
     #[must_use]
     pub fn is_file(&self) -> bool {
-        self.inner.is_file()
+        self.file_type().is_file()
     }
 
     /// Returns true if this metadata is for a symlink.
     #[must_use]
     pub fn is_symlink(&self) -> bool {
-        self.inner.is_symlink()
+        self.file_type().is_symlink()
     }
 
     /// Returns the length of the file, in bytes.
     #[must_use]
     pub fn len(&self) -> u64 {
-        self.inner.len()
+        match &self.inner {
+            MetadataInner::Std(inner) => inner.len(),
+            MetadataInner::Virtual(inner) => inner.len,
+        }
     }
 
     /// Returns true if the file is empty.
@@ -59,55 +96,91 @@ impl Metadata {
     /// Returns the file permissions.
     #[must_use]
     pub fn permissions(&self) -> Permissions {
-        Permissions {
-            inner: self.inner.permissions(),
+        match &self.inner {
+            MetadataInner::Std(inner) => Permissions {
+                inner: inner.permissions(),
+            },
+            MetadataInner::Virtual(inner) => inner.permissions.clone(),
         }
     }
 
     /// Returns the last modification time.
     pub fn modified(&self) -> io::Result<SystemTime> {
-        self.inner.modified()
+        match &self.inner {
+            MetadataInner::Std(inner) => inner.modified(),
+            MetadataInner::Virtual(inner) => Ok(inner.modified),
+        }
     }
 
     /// Returns the last access time.
     pub fn accessed(&self) -> io::Result<SystemTime> {
-        self.inner.accessed()
+        match &self.inner {
+            MetadataInner::Std(inner) => inner.accessed(),
+            MetadataInner::Virtual(inner) => Ok(inner.modified),
+        }
     }
 
     /// Returns the creation time.
     pub fn created(&self) -> io::Result<SystemTime> {
-        self.inner.created()
+        match &self.inner {
+            MetadataInner::Std(inner) => inner.created(),
+            MetadataInner::Virtual(inner) => Ok(inner.created),
+        }
     }
 }
 
 /// File type wrapper.
 #[derive(Debug, Clone)]
 pub struct FileType {
-    inner: std::fs::FileType,
+    inner: FileTypeInner,
+}
+
+#[derive(Debug, Clone)]
+enum FileTypeInner {
+    Std(std::fs::FileType),
+    Virtual(VirtualFileKind),
 }
 
 impl FileType {
     /// Wraps a `std::fs::FileType`.
     pub(crate) fn from_std(inner: std::fs::FileType) -> Self {
-        Self { inner }
+        Self {
+            inner: FileTypeInner::Std(inner),
+        }
+    }
+
+    /// Wraps the file type of a simulated node.
+    pub(crate) fn from_virtual(kind: VirtualFileKind) -> Self {
+        Self {
+            inner: FileTypeInner::Virtual(kind),
+        }
     }
 
     /// Returns true if this file type is a directory.
     #[must_use]
     pub fn is_dir(&self) -> bool {
-        self.inner.is_dir()
+        match &self.inner {
+            FileTypeInner::Std(inner) => inner.is_dir(),
+            FileTypeInner::Virtual(kind) => *kind == VirtualFileKind::Dir,
+        }
     }
 
     /// Returns true if this file type is a regular file.
     #[must_use]
     pub fn is_file(&self) -> bool {
-        self.inner.is_file()
+        match &self.inner {
+            FileTypeInner::Std(inner) => inner.is_file(),
+            FileTypeInner::Virtual(kind) => *kind == VirtualFileKind::File,
+        }
     }
 
     /// Returns true if this file type is a symlink.
     #[must_use]
     pub fn is_symlink(&self) -> bool {
-        self.inner.is_symlink()
+        match &self.inner {
+            FileTypeInner::Std(inner) => inner.is_symlink(),
+            FileTypeInner::Virtual(_) => false,
+        }
     }
 }
 
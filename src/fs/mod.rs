@@ -44,6 +44,11 @@
 //! - **Phase 0**: Synchronous I/O wrapped in async interface
 //! - **Phase 1+**: Use `spawn_blocking` for thread pool offload
 //! - **Future**: io_uring on Linux for true async I/O
+//!
+//! # I/O Scheduling
+//!
+//! An installed [`IoScheduler`] bounds concurrent reads, writes, and fsyncs
+//! per storage device; see [`io_scheduler`].
 
 mod buf_reader;
 mod buf_writer;
@@ -51,6 +56,7 @@ mod dir;
 mod file;
 #[cfg(test)]
 mod file_concurrent_test;
+pub mod io_scheduler;
 mod lines;
 mod metadata;
 mod open_options;
@@ -69,8 +75,13 @@ pub use file::File;
 #[cfg(feature = "test-internals")]
 #[doc(hidden)]
 pub use file::FileCursorOperationProbe;
+pub use io_scheduler::{
+    ClassLimits, DeviceLimits, IoClass, IoPermit, IoPriority, IoRequest, IoScheduler,
+    IoSchedulerConfig, IoSchedulerSnapshot, ScheduledVfs,
+};
 pub use lines::Lines;
 pub use metadata::{FileType, Metadata, Permissions};
+pub(crate) use metadata::{VirtualFileKind, VirtualMetadata};
 pub use open_options::OpenOptions;
 #[cfg(feature = "test-internals")]
 #[doc(hidden)]
@@ -4,6 +4,7 @@
 //! open behavior. The API mirrors `std::fs::OpenOptions`.
 
 use super::File;
+use crate::error::map_fd_exhaustion;
 use crate::runtime::spawn_blocking_io;
 use std::io;
 use std::path::Path;
@@ -40,6 +41,20 @@ pub struct OpenOptions {
     custom_flags: Option<i32>,
 }
 
+/// Plain copy of the [`OpenOptions`] flags.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct OpenFlags {
+    pub(crate) read: bool,
+    pub(crate) write: bool,
+    pub(crate) append: bool,
+    pub(crate) truncate: bool,
+    pub(crate) create: bool,
+    pub(crate) create_new: bool,
+    #[cfg(unix)]
+    pub(crate) mode: Option<u32>,
+}
+
 impl OpenOptions {
     /// Creates a new set of options with default settings.
     ///
@@ -137,13 +152,31 @@ impl OpenOptions {
     /// dropped. A successfully opened but unobserved handle is closed when the
     /// discarded result is dropped.
     pub async fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Fs)?;
         let path = path.as_ref().to_owned();
         let opts = self.clone();
 
-        let std_file = spawn_blocking_io(move || opts.to_std_options().open(&path)).await?;
+        let std_file = spawn_blocking_io(move || opts.to_std_options().open(&path))
+            .await
+            .map_err(map_fd_exhaustion)?;
         Ok(File::from_std(std_file))
     }
 
+    /// Returns the access and creation flags, for [`Vfs`](super::Vfs)
+    /// backends that interpret them without going through `std::fs`.
+    pub(crate) fn flags(&self) -> OpenFlags {
+        OpenFlags {
+            read: self.read,
+            write: self.write,
+            append: self.append,
+            truncate: self.truncate,
+            create: self.create,
+            create_new: self.create_new,
+            #[cfg(unix)]
+            mode: self.mode,
+        }
+    }
+
     /// Converts these options to `std::fs::OpenOptions`.
     fn to_std_options(&self) -> std::fs::OpenOptions {
         let mut opts = std::fs::OpenOptions::new();
@@ -4,6 +4,7 @@
 //! `rename` uses `IORING_OP_RENAMEAT`, and `symlink` uses `IORING_OP_SYMLINKAT`.
 //! Other operations use `spawn_blocking_io` for true async offloading.
 
+use super::io_scheduler::{IoClass, IoRequest, admit};
 use super::metadata::{Metadata, Permissions};
 use crate::runtime::spawn_blocking_io;
 use std::io;
@@ -258,7 +259,12 @@ pub async fn copy(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<u6
 
     let src = src_path.to_owned();
     let dst = dst_path.to_owned();
-    spawn_blocking_io(move || std::fs::copy(&src, &dst)).await
+    let permit = admit(IoRequest::read(src.clone()).with(dst.clone(), IoClass::Write)).await;
+    spawn_blocking_io(move || {
+        let _permit = permit;
+        std::fs::copy(&src, &dst)
+    })
+    .await
 }
 
 /// Rename or move a file.
@@ -436,13 +442,23 @@ pub async fn symlink(original: impl AsRef<Path>, link: impl AsRef<Path>) -> io::
 /// Read an entire file into a byte vector.
 pub async fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
     let path = path.as_ref().to_owned();
-    spawn_blocking_io(move || std::fs::read(&path)).await
+    let permit = admit(IoRequest::read(path.clone())).await;
+    spawn_blocking_io(move || {
+        let _permit = permit;
+        std::fs::read(&path)
+    })
+    .await
 }
 
 /// Read an entire file into a string.
 pub async fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
     let path = path.as_ref().to_owned();
-    spawn_blocking_io(move || std::fs::read_to_string(&path)).await
+    let permit = admit(IoRequest::read(path.clone())).await;
+    spawn_blocking_io(move || {
+        let _permit = permit;
+        std::fs::read_to_string(&path)
+    })
+    .await
 }
 
 /// Write bytes to a file (creates or truncates).
@@ -465,7 +481,9 @@ pub async fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Re
 }
 
 async fn write_owned(path: PathBuf, contents: Vec<u8>, hook: OperationProbeHook) -> io::Result<()> {
+    let permit = admit(IoRequest::write(path.clone())).await;
     spawn_blocking_io(move || {
+        let _permit = permit;
         hook.block_until_released();
         let result = std::fs::write(&path, &contents);
         hook.mark_completed();
@@ -553,7 +571,12 @@ async fn stage_write_atomic_with_hook(
 
     let path = path_ref.to_owned();
     let contents = contents.as_ref().to_owned();
-    spawn_blocking_io(move || stage_write_atomic_blocking(&path, &contents, hook)).await
+    let permit = admit(IoRequest::write(path.clone()).with(path.clone(), IoClass::Fsync)).await;
+    spawn_blocking_io(move || {
+        let _permit = permit;
+        stage_write_atomic_blocking(&path, &contents, hook)
+    })
+    .await
 }
 
 /// Runs [`stage_write_atomic`] with a deterministic post-stage handshake.
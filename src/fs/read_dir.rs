@@ -31,10 +31,19 @@ type ReadDirFuture = dyn std::future::Future<
 enum ReadDirState {
     Idle(std::fs::ReadDir),
     Pending(Pin<Box<ReadDirFuture>>),
+    /// Entries listed up front by a simulated [`Vfs`](super::Vfs) backend.
+    Virtual(std::vec::IntoIter<DirEntry>),
     Done,
 }
 
 impl ReadDir {
+    /// Creates an iterator over entries already listed by a simulated backend.
+    pub(crate) fn from_virtual(entries: Vec<DirEntry>) -> Self {
+        Self {
+            state: ReadDirState::Virtual(entries.into_iter()),
+        }
+    }
+
     /// Returns the next directory entry.
     pub async fn next_entry(&mut self) -> io::Result<Option<DirEntry>> {
         std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
@@ -63,38 +72,70 @@ pub async fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
 /// A directory entry returned by [`ReadDir`].
 #[derive(Debug)]
 pub struct DirEntry {
+    inner: DirEntryInner,
+}
+
+#[derive(Debug)]
+enum DirEntryInner {
     // Keep the original std entry alive so metadata/file_type can be offloaded
     // without re-resolving the path and changing std::fs::DirEntry semantics.
-    inner: Arc<std::fs::DirEntry>,
+    Std(Arc<std::fs::DirEntry>),
+    // Simulated backends snapshot the entry's metadata when listing.
+    Virtual { path: PathBuf, metadata: Metadata },
 }
 
 impl DirEntry {
+    /// Creates an entry listed by a simulated backend.
+    pub(crate) fn from_virtual(path: PathBuf, metadata: Metadata) -> Self {
+        Self {
+            inner: DirEntryInner::Virtual { path, metadata },
+        }
+    }
+
     /// Returns the full path to the entry.
     #[must_use]
     pub fn path(&self) -> PathBuf {
-        self.inner.path()
+        match &self.inner {
+            DirEntryInner::Std(inner) => inner.path(),
+            DirEntryInner::Virtual { path, .. } => path.clone(),
+        }
     }
 
     /// Returns the file name of the entry.
     #[must_use]
     pub fn file_name(&self) -> OsString {
-        self.inner.file_name()
+        match &self.inner {
+            DirEntryInner::Std(inner) => inner.file_name(),
+            DirEntryInner::Virtual { path, .. } => {
+                path.file_name().map(OsString::from).unwrap_or_default()
+            }
+        }
     }
 
     /// Returns the metadata for the entry.
     pub async fn metadata(&self) -> io::Result<Metadata> {
-        let inner = Arc::clone(&self.inner);
-        spawn_blocking_io(move || inner.metadata())
-            .await
-            .map(Metadata::from_std)
+        match &self.inner {
+            DirEntryInner::Std(inner) => {
+                let inner = Arc::clone(inner);
+                spawn_blocking_io(move || inner.metadata())
+                    .await
+                    .map(Metadata::from_std)
+            }
+            DirEntryInner::Virtual { metadata, .. } => Ok(metadata.clone()),
+        }
     }
 
     /// Returns the file type for the entry.
     pub async fn file_type(&self) -> io::Result<FileType> {
-        let inner = Arc::clone(&self.inner);
-        spawn_blocking_io(move || inner.file_type())
-            .await
-            .map(FileType::from_std)
+        match &self.inner {
+            DirEntryInner::Std(inner) => {
+                let inner = Arc::clone(inner);
+                spawn_blocking_io(move || inner.file_type())
+                    .await
+                    .map(FileType::from_std)
+            }
+            DirEntryInner::Virtual { metadata, .. } => Ok(metadata.file_type()),
+        }
     }
 }
 
@@ -115,7 +156,7 @@ impl Stream for ReadDir {
                     Poll::Ready(Ok((Some(Ok(entry)), inner))) => {
                         self.state = ReadDirState::Idle(inner);
                         return Poll::Ready(Some(Ok(DirEntry {
-                            inner: Arc::new(entry),
+                            inner: DirEntryInner::Std(Arc::new(entry)),
                         })));
                     }
                     Poll::Ready(Ok((Some(Err(err)), inner))) => {
@@ -134,6 +175,13 @@ impl Stream for ReadDir {
                         return Poll::Pending;
                     }
                 },
+                ReadDirState::Virtual(mut entries) => {
+                    let next = entries.next();
+                    if next.is_some() {
+                        self.state = ReadDirState::Virtual(entries);
+                    }
+                    return Poll::Ready(next.map(Ok));
+                }
                 ReadDirState::Done => {
                     return Poll::Ready(None);
                 }
@@ -2,7 +2,8 @@
 //!
 //! Provides `Vfs` and `VfsFile` traits that abstract filesystem operations,
 //! enabling real Unix I/O via [`UnixVfs`] and alternate implementations for tests
-//! or embedded environments.
+//! or embedded environments, such as the deterministic lab simulation
+//! `lab::sim_fs::SimFs`.
 //!
 //! # Design
 //!
@@ -13,6 +13,19 @@
 //! runtime. They build on the same two-phase mailbox and supervision infrastructure
 //! as plain actors.
 //!
+//! # Cast flow control
+//!
+//! Mailboxes are bounded. A [`MailboxSpec`] passed to
+//! [`Scope::spawn_gen_server_with_mailbox`](crate::cx::Scope::spawn_gen_server_with_mailbox)
+//! declares the capacity and how `cast` behaves when it is full
+//! ([`CastAdmission`]): wait up to the sender's deadline, fail fast with
+//! [`CastError::Full`], or shed the oldest queued cast. Producers can pace
+//! themselves with [`GenServerRef::queue_depth_hint`] and
+//! [`GenServerRef::ready_hint`] before reaching the limit, and
+//! [`GenServerRef::mailbox_metrics`] reports depth, rejections, sheds, and
+//! producer wait time. An unbounded mailbox is available only through the
+//! explicit [`MailboxSpec::unbounded`] constructor.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -58,7 +71,9 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::task::{Poll, Waker};
+use std::time::Duration;
 
 use crate::actor::{ActorId, ActorState};
 use crate::channel::mpsc;
@@ -221,6 +236,280 @@ impl std::fmt::Display for CastOverflowPolicy {
     }
 }
 
+// ============================================================================
+// Cast admission and mailbox flow control
+// ============================================================================
+
+/// How `cast` admits a message into a full mailbox.
+///
+/// `try_cast` never waits: under `Block` and `FailFast` it returns
+/// [`CastError::Full`], and under `ShedOldest` it evicts exactly like
+/// [`CastOverflowPolicy::DropOldest`]. Calls and info messages always wait
+/// for space and are never shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CastAdmission {
+    /// Wait for mailbox space.
+    ///
+    /// The wait honors the sender's deadline ([`Cx::deadline`]) and fails
+    /// with [`CastError::DeadlineExceeded`] once it passes.
+    #[default]
+    Block,
+
+    /// Fail immediately with [`CastError::Full`].
+    FailFast,
+
+    /// Evict the oldest queued cast to admit the new one.
+    ///
+    /// Every eviction is counted in [`MailboxMetrics::shed`], logged, and
+    /// traced as `gen_server::cast_evicted_oldest`.
+    ShedOldest,
+}
+
+impl CastAdmission {
+    /// The `try_cast` policy that matches this admission.
+    const fn overflow_policy(self) -> CastOverflowPolicy {
+        match self {
+            Self::Block | Self::FailFast => CastOverflowPolicy::Reject,
+            Self::ShedOldest => CastOverflowPolicy::DropOldest,
+        }
+    }
+}
+
+impl std::fmt::Display for CastAdmission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Block => write!(f, "Block"),
+            Self::FailFast => write!(f, "FailFast"),
+            Self::ShedOldest => write!(f, "ShedOldest"),
+        }
+    }
+}
+
+/// Mailbox capacity and cast admission declared when spawning a GenServer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MailboxSpec {
+    capacity: usize,
+    admission: CastAdmission,
+}
+
+impl MailboxSpec {
+    /// A mailbox holding at most `capacity` envelopes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[must_use]
+    pub const fn bounded(capacity: usize, admission: CastAdmission) -> Self {
+        assert!(capacity > 0, "mailbox capacity must be non-zero");
+        Self {
+            capacity,
+            admission,
+        }
+    }
+
+    /// A mailbox without a capacity limit.
+    ///
+    /// Casts never wait, fail, or shed, so a fast producer gets no
+    /// backpressure at all. Use this only when producers are bounded by
+    /// some other mechanism.
+    #[must_use]
+    pub const fn unbounded() -> Self {
+        Self {
+            capacity: usize::MAX,
+            admission: CastAdmission::Block,
+        }
+    }
+
+    /// Returns the mailbox capacity (`usize::MAX` when unbounded).
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the cast admission policy.
+    #[must_use]
+    pub const fn admission(&self) -> CastAdmission {
+        self.admission
+    }
+
+    /// Returns true for [`MailboxSpec::unbounded`].
+    #[must_use]
+    pub const fn is_unbounded(&self) -> bool {
+        self.capacity == usize::MAX
+    }
+}
+
+/// Flow-control snapshot for one GenServer mailbox.
+///
+/// Counters are shared by the handle and every [`GenServerRef`] of the
+/// server, so any of them reports the same totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MailboxMetrics {
+    /// Envelopes queued when the snapshot was taken.
+    pub depth: usize,
+    /// Mailbox capacity (`usize::MAX` when unbounded).
+    pub capacity: usize,
+    /// Casts refused because the mailbox was full.
+    pub admission_rejections: u64,
+    /// Queued casts evicted to admit newer ones.
+    pub shed: u64,
+    /// Casts that had to wait for mailbox space.
+    pub blocked_casts: u64,
+    /// Blocked casts that gave up when their deadline passed.
+    pub deadline_expirations: u64,
+    /// Total time producers spent waiting for mailbox space.
+    pub producer_wait: Duration,
+}
+
+/// Admission policy, counters, and `ready_hint` waiters shared by a server's
+/// handle, its refs, and its message loop.
+#[derive(Debug)]
+struct MailboxFlow {
+    admission: CastAdmission,
+    rejections: AtomicU64,
+    shed: AtomicU64,
+    blocked: AtomicU64,
+    expired: AtomicU64,
+    wait_nanos: AtomicU64,
+    ready_waiters: parking_lot::Mutex<Vec<Waker>>,
+    has_ready_waiters: AtomicBool,
+}
+
+impl MailboxFlow {
+    fn new(admission: CastAdmission) -> Self {
+        Self {
+            admission,
+            rejections: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+            blocked: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+            wait_nanos: AtomicU64::new(0),
+            ready_waiters: parking_lot::Mutex::new(Vec::new()),
+            has_ready_waiters: AtomicBool::new(false),
+        }
+    }
+
+    fn record_rejection(&self) {
+        self.rejections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_shed(&self) {
+        self.shed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_wait(&self, nanos: u64) {
+        self.wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    fn metrics<T>(&self, sender: &mpsc::Sender<T>) -> MailboxMetrics {
+        MailboxMetrics {
+            depth: sender.len(),
+            capacity: sender.capacity(),
+            admission_rejections: self.rejections.load(Ordering::Relaxed),
+            shed: self.shed.load(Ordering::Relaxed),
+            blocked_casts: self.blocked.load(Ordering::Relaxed),
+            deadline_expirations: self.expired.load(Ordering::Relaxed),
+            producer_wait: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Wakes `ready_hint` waiters after the server dequeued or stopped.
+    /// Costs one atomic load when nobody is waiting.
+    fn notify_dequeued(&self) {
+        if !self.has_ready_waiters.load(Ordering::Acquire) {
+            return;
+        }
+        let waiters = {
+            let mut waiters = self.ready_waiters.lock();
+            self.has_ready_waiters.store(false, Ordering::Release);
+            std::mem::take(&mut *waiters)
+        };
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+
+    fn poll_ready<T>(
+        &self,
+        sender: &mpsc::Sender<T>,
+        threshold: usize,
+        task_cx: &std::task::Context<'_>,
+    ) -> Poll<Result<(), CastError>> {
+        if sender.is_closed() {
+            return Poll::Ready(Err(CastError::ServerStopped));
+        }
+        if sender.len() <= threshold {
+            return Poll::Ready(Ok(()));
+        }
+        {
+            let mut waiters = self.ready_waiters.lock();
+            if !waiters.iter().any(|w| w.will_wake(task_cx.waker())) {
+                waiters.push(task_cx.waker().clone());
+            }
+            self.has_ready_waiters.store(true, Ordering::Release);
+        }
+        // Re-check after registering: a dequeue that raced with the check
+        // above either sees the registration or is visible here.
+        if sender.is_closed() {
+            Poll::Ready(Err(CastError::ServerStopped))
+        } else if sender.len() <= threshold {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Enqueues a cast under [`CastAdmission::Block`], waiting for mailbox space
+/// until the sender's deadline.
+async fn blocking_cast<S: GenServer>(
+    cx: &Cx,
+    sender: &mpsc::Sender<Envelope<S>>,
+    flow: &MailboxFlow,
+    envelope: Envelope<S>,
+) -> Result<(), CastError> {
+    let envelope = match sender.try_send(envelope) {
+        Ok(()) => return Ok(()),
+        Err(mpsc::SendError::Full(envelope)) => envelope,
+        Err(mpsc::SendError::Disconnected(_) | mpsc::SendError::Cancelled(_)) => {
+            cx.trace("gen_server::cast_send_failed");
+            return Err(CastError::ServerStopped);
+        }
+    };
+
+    flow.blocked.fetch_add(1, Ordering::Relaxed);
+    let started = cx.now();
+    let sent = match cx.deadline() {
+        Some(deadline) => {
+            let send = sender.send(cx, envelope);
+            if let Ok(sent) = crate::time::timeout_at(deadline, send).await {
+                sent
+            } else {
+                flow.record_wait(cx.now().duration_since(started));
+                flow.expired.fetch_add(1, Ordering::Relaxed);
+                cx.trace("gen_server::cast_deadline_exceeded");
+                return Err(CastError::DeadlineExceeded);
+            }
+        }
+        None => sender.send(cx, envelope).await,
+    };
+    flow.record_wait(cx.now().duration_since(started));
+
+    sent.map_err(|e| match e {
+        mpsc::SendError::Cancelled(_) => {
+            cx.trace("gen_server::cast_send_cancelled");
+            let reason = cx
+                .cancel_reason()
+                .unwrap_or_else(crate::types::CancelReason::parent_cancelled);
+            CastError::Cancelled(reason)
+        }
+        mpsc::SendError::Disconnected(_) | mpsc::SendError::Full(_) => {
+            cx.trace("gen_server::cast_send_failed");
+            CastError::ServerStopped
+        }
+    })
+}
+
 // ============================================================================
 // System messages (bd-188ey)
 // ============================================================================
@@ -746,6 +1035,7 @@ impl<S: GenServer> std::fmt::Debug for Envelope<S> {
 struct GenServerCell<S: GenServer> {
     mailbox: mpsc::Receiver<Envelope<S>>,
     state: Arc<GenServerStateCell>,
+    flow: Arc<MailboxFlow>,
     _keep_alive: mpsc::Sender<Envelope<S>>,
 }
 
@@ -813,6 +1103,7 @@ pub struct GenServerHandle<S: GenServer> {
     /// is available — the previous Cx-only `cx.trace(...)` route was
     /// invisible to sync callers, masking SLO-relevant lossiness.
     evicted_count: Arc<AtomicU64>,
+    flow: Arc<MailboxFlow>,
 }
 
 /// Error returned when a call fails.
@@ -847,6 +1138,8 @@ pub enum CastError {
     Full,
     /// The cast was cancelled.
     Cancelled(CancelReason),
+    /// The sender's deadline passed while waiting for mailbox space.
+    DeadlineExceeded,
 }
 
 impl std::fmt::Display for CastError {
@@ -855,6 +1148,9 @@ impl std::fmt::Display for CastError {
             Self::ServerStopped => write!(f, "GenServer has stopped"),
             Self::Full => write!(f, "GenServer mailbox full"),
             Self::Cancelled(reason) => write!(f, "GenServer cast cancelled: {reason}"),
+            Self::DeadlineExceeded => {
+                write!(f, "GenServer cast deadline exceeded waiting for mailbox space")
+            }
         }
     }
 }
@@ -999,6 +1295,10 @@ impl<S: GenServer> GenServerHandle<S> {
     }
 
     /// Send a cast (fire-and-forget) to the server.
+    ///
+    /// A full mailbox is handled by the server's [`CastAdmission`]: `Block`
+    /// waits for space until the sender's deadline, `FailFast` and
+    /// `ShedOldest` behave like [`try_cast`](Self::try_cast).
     pub async fn cast(&self, cx: &Cx, msg: S::Cast) -> Result<(), CastError> {
         if cx.checkpoint().is_err() {
             cx.trace("gen_server::cast_rejected_cancelled");
@@ -1015,20 +1315,11 @@ impl<S: GenServer> GenServerHandle<S> {
             cx.trace("gen_server::cast_rejected_stopped");
             return Err(CastError::ServerStopped);
         }
+        if self.flow.admission != CastAdmission::Block {
+            return self.try_cast(msg);
+        }
         let envelope: Envelope<S> = Envelope::Cast { msg };
-        self.sender.send(cx, envelope).await.map_err(|e| match e {
-            mpsc::SendError::Cancelled(_) => {
-                cx.trace("gen_server::cast_send_cancelled");
-                let reason = cx
-                    .cancel_reason()
-                    .unwrap_or_else(crate::types::CancelReason::parent_cancelled);
-                CastError::Cancelled(reason)
-            }
-            mpsc::SendError::Disconnected(_) | mpsc::SendError::Full(_) => {
-                cx.trace("gen_server::cast_send_failed");
-                CastError::ServerStopped
-            }
-        })
+        blocking_cast(cx, &self.sender, &self.flow, envelope).await
     }
 
     /// Try to send a cast without blocking.
@@ -1049,7 +1340,10 @@ impl<S: GenServer> GenServerHandle<S> {
                 mpsc::SendError::Disconnected(_) | mpsc::SendError::Cancelled(_) => {
                     CastError::ServerStopped
                 }
-                mpsc::SendError::Full(_) => CastError::Full,
+                mpsc::SendError::Full(_) => {
+                    self.flow.record_rejection();
+                    CastError::Full
+                }
             }),
             CastOverflowPolicy::DropOldest => {
                 match self.sender.send_evict_oldest_where(envelope, |queued| {
@@ -1069,6 +1363,7 @@ impl<S: GenServer> GenServerHandle<S> {
                         // 3. Preserve the existing `cx.trace(...)` breadcrumb when
                         //    a Cx IS available so structured-trace replay continues
                         //    to attribute the eviction to a region/task.
+                        self.flow.record_shed();
                         let _evicted_total = self
                             .evicted_count
                             .fetch_add(1, Ordering::Relaxed)
@@ -1087,7 +1382,10 @@ impl<S: GenServer> GenServerHandle<S> {
                     Err(mpsc::SendError::Disconnected(_) | mpsc::SendError::Cancelled(_)) => {
                         Err(CastError::ServerStopped)
                     }
-                    Err(mpsc::SendError::Full(_)) => Err(CastError::Full),
+                    Err(mpsc::SendError::Full(_)) => {
+                        self.flow.record_rejection();
+                        Err(CastError::Full)
+                    }
                 }
             }
         }
@@ -1148,6 +1446,53 @@ impl<S: GenServer> GenServerHandle<S> {
         self.overflow_policy
     }
 
+    /// Returns the cast admission policy for a full mailbox.
+    #[inline]
+    #[must_use]
+    pub fn cast_admission(&self) -> CastAdmission {
+        self.flow.admission
+    }
+
+    /// Returns the number of envelopes currently queued in the mailbox.
+    ///
+    /// This is a racy snapshot meant for producer pacing: it can change as
+    /// soon as it is read, and it counts calls and info messages too.
+    #[inline]
+    #[must_use]
+    pub fn queue_depth_hint(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Waits until the mailbox holds at most `threshold` envelopes.
+    ///
+    /// The server wakes waiters each time it dequeues, so a producer can
+    /// back off before the mailbox is full instead of hitting
+    /// [`CastError::Full`] or blocking in `cast`. Resolving is a hint, not a
+    /// reservation: other producers may fill the space first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CastError::Cancelled`] if `cx` is cancelled while waiting and
+    /// [`CastError::ServerStopped`] once the server stops accepting messages.
+    pub async fn ready_hint(&self, cx: &Cx, threshold: usize) -> Result<(), CastError> {
+        std::future::poll_fn(|task_cx| {
+            if cx.checkpoint().is_err() {
+                let reason = cx
+                    .cancel_reason()
+                    .unwrap_or_else(crate::types::CancelReason::parent_cancelled);
+                return Poll::Ready(Err(CastError::Cancelled(reason)));
+            }
+            self.flow.poll_ready(&self.sender, threshold, task_cx)
+        })
+        .await
+    }
+
+    /// Returns a flow-control snapshot of the mailbox.
+    #[must_use]
+    pub fn mailbox_metrics(&self) -> MailboxMetrics {
+        self.flow.metrics(&self.sender)
+    }
+
     /// Returns the server's actor ID.
     #[inline]
     #[must_use]
@@ -1321,6 +1666,7 @@ pub struct GenServerRef<S: GenServer> {
     sender: mpsc::Sender<Envelope<S>>,
     state: Arc<GenServerStateCell>,
     overflow_policy: CastOverflowPolicy,
+    flow: Arc<MailboxFlow>,
 }
 
 impl<S: GenServer> Clone for GenServerRef<S> {
@@ -1330,6 +1676,7 @@ impl<S: GenServer> Clone for GenServerRef<S> {
             sender: self.sender.clone(),
             state: Arc::clone(&self.state),
             overflow_policy: self.overflow_policy,
+            flow: Arc::clone(&self.flow),
         }
     }
 }
@@ -1341,6 +1688,53 @@ impl<S: GenServer> GenServerRef<S> {
         self.overflow_policy
     }
 
+    /// Returns the cast admission policy for a full mailbox.
+    #[inline]
+    #[must_use]
+    pub fn cast_admission(&self) -> CastAdmission {
+        self.flow.admission
+    }
+
+    /// Returns the number of envelopes currently queued in the mailbox.
+    ///
+    /// This is a racy snapshot meant for producer pacing: it can change as
+    /// soon as it is read, and it counts calls and info messages too.
+    #[inline]
+    #[must_use]
+    pub fn queue_depth_hint(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Waits until the mailbox holds at most `threshold` envelopes.
+    ///
+    /// The server wakes waiters each time it dequeues, so a producer can
+    /// back off before the mailbox is full instead of hitting
+    /// [`CastError::Full`] or blocking in `cast`. Resolving is a hint, not a
+    /// reservation: other producers may fill the space first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CastError::Cancelled`] if `cx` is cancelled while waiting and
+    /// [`CastError::ServerStopped`] once the server stops accepting messages.
+    pub async fn ready_hint(&self, cx: &Cx, threshold: usize) -> Result<(), CastError> {
+        std::future::poll_fn(|task_cx| {
+            if cx.checkpoint().is_err() {
+                let reason = cx
+                    .cancel_reason()
+                    .unwrap_or_else(crate::types::CancelReason::parent_cancelled);
+                return Poll::Ready(Err(CastError::Cancelled(reason)));
+            }
+            self.flow.poll_ready(&self.sender, threshold, task_cx)
+        })
+        .await
+    }
+
+    /// Returns a flow-control snapshot of the mailbox.
+    #[must_use]
+    pub fn mailbox_metrics(&self) -> MailboxMetrics {
+        self.flow.metrics(&self.sender)
+    }
+
     /// Send a call to the server.
     pub async fn call(&self, cx: &Cx, request: S::Call) -> Result<S::Reply, CallError> {
         if cx.checkpoint().is_err() {
@@ -1435,6 +1829,9 @@ impl<S: GenServer> GenServerRef<S> {
     }
 
     /// Send a cast to the server.
+    ///
+    /// A full mailbox is handled by the server's [`CastAdmission`], exactly
+    /// as in [`GenServerHandle::cast`].
     pub async fn cast(&self, cx: &Cx, msg: S::Cast) -> Result<(), CastError> {
         if cx.checkpoint().is_err() {
             cx.trace("gen_server::cast_rejected_cancelled");
@@ -1451,20 +1848,11 @@ impl<S: GenServer> GenServerRef<S> {
             cx.trace("gen_server::cast_rejected_stopped");
             return Err(CastError::ServerStopped);
         }
+        if self.flow.admission != CastAdmission::Block {
+            return self.try_cast(msg);
+        }
         let envelope: Envelope<S> = Envelope::Cast { msg };
-        self.sender.send(cx, envelope).await.map_err(|e| match e {
-            mpsc::SendError::Cancelled(_) => {
-                cx.trace("gen_server::cast_send_cancelled");
-                let reason = cx
-                    .cancel_reason()
-                    .unwrap_or_else(crate::types::CancelReason::parent_cancelled);
-                CastError::Cancelled(reason)
-            }
-            mpsc::SendError::Disconnected(_) | mpsc::SendError::Full(_) => {
-                cx.trace("gen_server::cast_send_failed");
-                CastError::ServerStopped
-            }
-        })
+        blocking_cast(cx, &self.sender, &self.flow, envelope).await
     }
 
     /// Try to send a cast without blocking.
@@ -1483,7 +1871,10 @@ impl<S: GenServer> GenServerRef<S> {
                 mpsc::SendError::Disconnected(_) | mpsc::SendError::Cancelled(_) => {
                     CastError::ServerStopped
                 }
-                mpsc::SendError::Full(_) => CastError::Full,
+                mpsc::SendError::Full(_) => {
+                    self.flow.record_rejection();
+                    CastError::Full
+                }
             }),
             CastOverflowPolicy::DropOldest => match self
                 .sender
@@ -1491,6 +1882,11 @@ impl<S: GenServer> GenServerRef<S> {
             {
                 Ok(Some(evicted)) => {
                     debug_assert!(matches!(evicted, Envelope::Cast { .. }));
+                    self.flow.record_shed();
+                    crate::tracing_compat::warn!(
+                        actor_id = ?self.actor_id,
+                        "gen_server::cast_evicted_oldest"
+                    );
                     if let Some(cx) = Cx::current() {
                         cx.trace("gen_server::cast_evicted_oldest");
                     }
@@ -1500,7 +1896,10 @@ impl<S: GenServer> GenServerRef<S> {
                 Err(mpsc::SendError::Disconnected(_) | mpsc::SendError::Cancelled(_)) => {
                     Err(CastError::ServerStopped)
                 }
-                Err(mpsc::SendError::Full(_)) => Err(CastError::Full),
+                Err(mpsc::SendError::Full(_)) => {
+                    self.flow.record_rejection();
+                    Err(CastError::Full)
+                }
             },
         }
     }
@@ -1585,6 +1984,7 @@ impl<S: GenServer> GenServerHandle<S> {
             sender: self.sender.clone(),
             state: Arc::clone(&self.state),
             overflow_policy: self.overflow_policy,
+            flow: Arc::clone(&self.flow),
         }
     }
 }
@@ -1646,6 +2046,7 @@ async fn run_gen_server_loop<S: GenServer>(
 
         match recv_result {
             Ok(envelope) => {
+                cell.flow.notify_dequeued();
                 dispatch_envelope(&mut server, &cx, envelope).await;
 
                 // Yield periodically to maintain fairness with other tasks
@@ -1697,6 +2098,7 @@ async fn run_gen_server_loop<S: GenServer>(
     // Calls during drain: reply with error (caller should not depend on drain).
     // Casts during drain: process normally if gracefully stopped, skip if aborted.
     cell.mailbox.close();
+    cell.flow.notify_dequeued();
 
     let mut drained: u64 = 0;
     let mut drain_yield_counter = 0u32;
@@ -1770,19 +2172,62 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
     /// Spawns a new GenServer in this scope.
     ///
     /// The server runs as a region-owned task. Calls and casts are delivered
-    /// through a bounded MPSC channel with two-phase send semantics.
+    /// through a bounded MPSC channel with two-phase send semantics. `cast`
+    /// waits for mailbox space ([`CastAdmission::Block`]) and `try_cast`
+    /// follows [`GenServer::cast_overflow_policy`].
     pub fn spawn_gen_server<S: GenServer>(
         &self,
         state: &mut crate::runtime::state::RuntimeState,
         cx: &Cx,
         server: S,
         mailbox_capacity: usize,
+    ) -> Result<(GenServerHandle<S>, crate::runtime::stored_task::StoredTask), SpawnError> {
+        let overflow_policy = server.cast_overflow_policy();
+        self.spawn_gen_server_with_flow(
+            state,
+            cx,
+            server,
+            mailbox_capacity,
+            CastAdmission::Block,
+            overflow_policy,
+        )
+    }
+
+    /// Spawns a new GenServer whose mailbox follows `spec`.
+    ///
+    /// The spec's [`CastAdmission`] governs both `cast` and `try_cast` and
+    /// takes precedence over [`GenServer::cast_overflow_policy`].
+    pub fn spawn_gen_server_with_mailbox<S: GenServer>(
+        &self,
+        state: &mut crate::runtime::state::RuntimeState,
+        cx: &Cx,
+        server: S,
+        spec: MailboxSpec,
+    ) -> Result<(GenServerHandle<S>, crate::runtime::stored_task::StoredTask), SpawnError> {
+        self.spawn_gen_server_with_flow(
+            state,
+            cx,
+            server,
+            spec.capacity(),
+            spec.admission(),
+            spec.admission().overflow_policy(),
+        )
+    }
+
+    fn spawn_gen_server_with_flow<S: GenServer>(
+        &self,
+        state: &mut crate::runtime::state::RuntimeState,
+        cx: &Cx,
+        server: S,
+        mailbox_capacity: usize,
+        admission: CastAdmission,
+        overflow_policy: CastOverflowPolicy,
     ) -> Result<(GenServerHandle<S>, crate::runtime::stored_task::StoredTask), SpawnError> {
         use crate::cx::scope::CatchUnwind;
         use crate::runtime::stored_task::StoredTask;
         use crate::tracing_compat::{debug, debug_span};
 
-        let overflow_policy = server.cast_overflow_policy();
+        let flow = Arc::new(MailboxFlow::new(admission));
         let (msg_tx, msg_rx) = mpsc::channel::<Envelope<S>>(mailbox_capacity);
         let (result_tx, result_rx) = oneshot::channel::<Result<S, JoinError>>();
         let task_id = self.create_task_record(state)?;
@@ -1813,12 +2258,13 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
         let mut cell = GenServerCell {
             mailbox: msg_rx,
             state: Arc::clone(&server_state),
+            flow: Arc::clone(&flow),
             _keep_alive: msg_tx.clone(),
         };
 
         let wrapped = async move {
             spawn_effects.dispatch();
-            let trace_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let trace_result = crate::runtime::panic_strategy::catch_unwind(|| {
                 let _span = debug_span!(
                     "gen_server_spawn",
                     task_id = ?task_id,
@@ -1832,7 +2278,7 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
                     mailbox_capacity = mailbox_capacity,
                     "gen_server spawned"
                 );
-            }));
+            });
             let result = match trace_result {
                 Ok(()) => {
                     CatchUnwind {
@@ -1862,6 +2308,7 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
                     // the executor drop path). Close and abort queued call
                     // permits here, mirroring the graceful drain.
                     cell.mailbox.close();
+                    cell.flow.notify_dequeued();
                     while let Ok(envelope) = cell.mailbox.try_recv() {
                         if let Envelope::Call { reply_permit, .. } = envelope {
                             let _ = session::TrackedOneshotPermit::abort(reply_permit);
@@ -1890,6 +2337,7 @@ impl<P: crate::types::Policy> crate::cx::Scope<'_, P> {
             completed: false,
             overflow_policy,
             evicted_count: Arc::new(AtomicU64::new(0)),
+            flow,
         };
 
         Ok((handle, stored))
@@ -7451,4 +7899,286 @@ mod conformance_integration {
 
         crate::test_complete!("gen_server_conformance_suite");
     }
+
+    // ---- Cast flow control ----
+
+    /// Records every cast it handles, in order.
+    #[derive(Debug, Default)]
+    struct CastLog {
+        seen: Vec<u64>,
+    }
+
+    impl GenServer for CastLog {
+        type Call = ();
+        type Reply = usize;
+        type Cast = u64;
+        type Info = SystemMsg;
+
+        fn handle_call(
+            &mut self,
+            _cx: &Cx,
+            _request: (),
+            reply: Reply<usize>,
+        ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            let _ = reply.send(self.seen.len());
+            Box::pin(async {})
+        }
+
+        fn handle_cast(
+            &mut self,
+            _cx: &Cx,
+            msg: u64,
+        ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            self.seen.push(msg);
+            Box::pin(async {})
+        }
+    }
+
+    fn stop_and_join_cast_log(
+        runtime: &mut crate::lab::LabRuntime,
+        mut handle: GenServerHandle<CastLog>,
+        cx: &Cx,
+    ) -> Vec<u64> {
+        let server_task_id = handle.task_id();
+        handle.stop();
+        runtime.scheduler.lock().schedule(server_task_id, 0);
+        runtime.run_until_quiescent();
+        futures_lite::future::block_on(handle.join(cx))
+            .expect("server join ok")
+            .seen
+    }
+
+    #[test]
+    fn mailbox_spec_accessors_and_admission_display() {
+        init_test("mailbox_spec_accessors_and_admission_display");
+
+        let bounded = MailboxSpec::bounded(8, CastAdmission::FailFast);
+        assert_eq!(bounded.capacity(), 8);
+        assert_eq!(bounded.admission(), CastAdmission::FailFast);
+        assert!(!bounded.is_unbounded());
+
+        let unbounded = MailboxSpec::unbounded();
+        assert_eq!(unbounded.capacity(), usize::MAX);
+        assert_eq!(unbounded.admission(), CastAdmission::Block);
+        assert!(unbounded.is_unbounded());
+
+        assert_eq!(CastAdmission::default(), CastAdmission::Block);
+        assert_eq!(CastAdmission::ShedOldest.to_string(), "ShedOldest");
+        assert_eq!(
+            CastError::DeadlineExceeded.to_string(),
+            "GenServer cast deadline exceeded waiting for mailbox space"
+        );
+
+        crate::test_complete!("mailbox_spec_accessors_and_admission_display");
+    }
+
+    #[test]
+    #[should_panic(expected = "mailbox capacity must be non-zero")]
+    fn mailbox_spec_rejects_zero_capacity() {
+        let _ = MailboxSpec::bounded(0, CastAdmission::Block);
+    }
+
+    #[test]
+    fn cast_fail_fast_rejects_when_mailbox_full() {
+        init_test("cast_fail_fast_rejects_when_mailbox_full");
+
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::new(0x7180_0001));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let spec = MailboxSpec::bounded(2, CastAdmission::FailFast);
+        let (handle, stored) = scope
+            .spawn_gen_server_with_mailbox(&mut runtime.state, &cx, CastLog::default(), spec)
+            .unwrap();
+        runtime.state.store_spawned_task(handle.task_id(), stored);
+
+        futures_lite::future::block_on(handle.cast(&cx, 1)).expect("first cast");
+        futures_lite::future::block_on(handle.cast(&cx, 2)).expect("second cast");
+        let third = futures_lite::future::block_on(handle.cast(&cx, 3));
+        crate::assert_with_log!(
+            matches!(third, Err(CastError::Full)),
+            "cast fails fast on a full mailbox",
+            "Err(Full)",
+            format!("{third:?}")
+        );
+        assert!(matches!(handle.try_cast(4), Err(CastError::Full)));
+
+        let metrics = handle.server_ref().mailbox_metrics();
+        assert_eq!(metrics.depth, 2);
+        assert_eq!(metrics.capacity, 2);
+        assert_eq!(metrics.admission_rejections, 2);
+        assert_eq!(metrics.shed, 0);
+        assert_eq!(metrics.blocked_casts, 0);
+        assert_eq!(handle.cast_admission(), CastAdmission::FailFast);
+
+        let seen = stop_and_join_cast_log(&mut runtime, handle, &cx);
+        crate::assert_with_log!(seen == vec![1, 2], "admitted casts", vec![1, 2], seen);
+
+        crate::test_complete!("cast_fail_fast_rejects_when_mailbox_full");
+    }
+
+    #[test]
+    fn cast_shed_oldest_evicts_and_counts_shed_events() {
+        init_test("cast_shed_oldest_evicts_and_counts_shed_events");
+
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::new(0x7180_0002));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let spec = MailboxSpec::bounded(2, CastAdmission::ShedOldest);
+        let (handle, stored) = scope
+            .spawn_gen_server_with_mailbox(&mut runtime.state, &cx, CastLog::default(), spec)
+            .unwrap();
+        runtime.state.store_spawned_task(handle.task_id(), stored);
+        let server_ref = handle.server_ref();
+
+        for value in 1..=3 {
+            futures_lite::future::block_on(handle.cast(&cx, value)).expect("handle cast");
+        }
+        server_ref.try_cast(4).expect("ref try_cast sheds");
+
+        assert_eq!(handle.cast_overflow_policy(), CastOverflowPolicy::DropOldest);
+        assert_eq!(handle.evicted_count(), 1, "handle counts its own evictions");
+        let metrics = handle.mailbox_metrics();
+        crate::assert_with_log!(metrics.shed == 2, "shared shed count", 2, metrics.shed);
+        assert_eq!(metrics.depth, 2);
+        assert_eq!(metrics.admission_rejections, 0);
+        assert_eq!(server_ref.mailbox_metrics(), metrics);
+
+        let seen = stop_and_join_cast_log(&mut runtime, handle, &cx);
+        crate::assert_with_log!(seen == vec![3, 4], "newest casts kept", vec![3, 4], seen);
+
+        crate::test_complete!("cast_shed_oldest_evicts_and_counts_shed_events");
+    }
+
+    #[test]
+    fn blocked_cast_fails_at_sender_deadline() {
+        init_test("blocked_cast_fails_at_sender_deadline");
+
+        let config = crate::lab::LabConfig::new(0x7180_0003).with_auto_advance();
+        let mut runtime = crate::lab::LabRuntime::new(config);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let spec = MailboxSpec::bounded(1, CastAdmission::Block);
+        let (handle, stored) = scope
+            .spawn_gen_server_with_mailbox(&mut runtime.state, &cx, CastLog::default(), spec)
+            .unwrap();
+        runtime.state.store_spawned_task(handle.task_id(), stored);
+
+        let server_ref = handle.server_ref();
+        let outcome: Arc<Mutex<Option<Result<(), CastError>>>> = Arc::new(Mutex::new(None));
+        let outcome_slot = Arc::clone(&outcome);
+        let budget = Budget::INFINITE.with_deadline(Time::from_millis(10));
+        let (producer, _producer_handle) = runtime
+            .state
+            .create_task(region, budget, async move {
+                let cx = Cx::current().expect("producer cx");
+                server_ref.cast(&cx, 1).await.expect("first cast fits");
+                let blocked = server_ref.cast(&cx, 2).await;
+                *outcome_slot.lock() = Some(blocked);
+            })
+            .expect("create producer");
+
+        // The server is never scheduled here, so the second cast can only
+        // end by reaching the producer's deadline.
+        runtime.scheduler.lock().schedule(producer, 0);
+        runtime.run_with_auto_advance();
+
+        let blocked = outcome.lock().take();
+        crate::assert_with_log!(
+            matches!(blocked, Some(Err(CastError::DeadlineExceeded))),
+            "blocked cast expires",
+            "Some(Err(DeadlineExceeded))",
+            format!("{blocked:?}")
+        );
+        let metrics = handle.mailbox_metrics();
+        assert_eq!(metrics.blocked_casts, 1);
+        assert_eq!(metrics.deadline_expirations, 1);
+        assert_eq!(metrics.depth, 1);
+
+        let seen = stop_and_join_cast_log(&mut runtime, handle, &cx);
+        crate::assert_with_log!(seen == vec![1], "only the admitted cast", vec![1], seen);
+
+        crate::test_complete!("blocked_cast_fails_at_sender_deadline");
+    }
+
+    #[test]
+    fn ready_hint_wakes_after_server_drains_below_threshold() {
+        init_test("ready_hint_wakes_after_server_drains_below_threshold");
+
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::new(0x7180_0004));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let spec = MailboxSpec::bounded(4, CastAdmission::Block);
+        let (handle, stored) = scope
+            .spawn_gen_server_with_mailbox(&mut runtime.state, &cx, CastLog::default(), spec)
+            .unwrap();
+        let server_task_id = handle.task_id();
+        runtime.state.store_spawned_task(server_task_id, stored);
+
+        for value in 0..4 {
+            handle.try_cast(value).expect("fill mailbox");
+        }
+        assert_eq!(handle.queue_depth_hint(), 4);
+
+        let server_ref = handle.server_ref();
+        let woke_at_depth: Arc<Mutex<Option<usize>>> = Arc::new(Mutex::new(None));
+        let woke_slot = Arc::clone(&woke_at_depth);
+        let (producer, _producer_handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("producer cx");
+                server_ref.ready_hint(&cx, 1).await.expect("ready");
+                *woke_slot.lock() = Some(server_ref.queue_depth_hint());
+            })
+            .expect("create producer");
+
+        runtime.scheduler.lock().schedule(producer, 0);
+        runtime.run_until_quiescent();
+        assert!(
+            woke_at_depth.lock().is_none(),
+            "ready_hint must wait while the mailbox is above the threshold"
+        );
+
+        runtime.scheduler.lock().schedule(server_task_id, 0);
+        runtime.run_until_quiescent();
+        let depth = *woke_at_depth.lock();
+        crate::assert_with_log!(
+            depth.is_some_and(|depth| depth <= 1),
+            "ready_hint resolves once depth <= threshold",
+            "Some(<= 1)",
+            depth
+        );
+        assert_eq!(handle.queue_depth_hint(), 0);
+
+        let seen = stop_and_join_cast_log(&mut runtime, handle, &cx);
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+
+        crate::test_complete!("ready_hint_wakes_after_server_drains_below_threshold");
+    }
+
+    #[test]
+    fn ready_hint_reports_stopped_server() {
+        init_test("ready_hint_reports_stopped_server");
+
+        let mut runtime = crate::lab::LabRuntime::new(crate::lab::LabConfig::new(0x7180_0005));
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+        let (handle, stored) = scope
+            .spawn_gen_server(&mut runtime.state, &cx, CastLog::default(), 1)
+            .unwrap();
+        runtime.state.store_spawned_task(handle.task_id(), stored);
+        let server_ref = handle.server_ref();
+        handle.try_cast(7).expect("fill mailbox");
+
+        let seen = stop_and_join_cast_log(&mut runtime, handle, &cx);
+        assert_eq!(seen, vec![7]);
+        let ready = futures_lite::future::block_on(server_ref.ready_hint(&cx, 0));
+        assert!(matches!(ready, Err(CastError::ServerStopped)));
+
+        crate::test_complete!("ready_hint_reports_stopped_server");
+    }
 }
@@ -12,6 +12,7 @@ use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 
 use crate::bytes::Bytes;
+use crate::net::endpoint_set::{EndpointLease, EndpointSet};
 
 use super::codec::{Codec, FramedCodec, IdentityCodec};
 use super::status::{GrpcError, Status, TransportErrorKind};
@@ -163,6 +164,8 @@ pub struct ChannelBuilder {
     uri: String,
     /// Channel configuration.
     config: ChannelConfig,
+    /// Balanced endpoints for new subchannels.
+    endpoints: Option<EndpointSet>,
 }
 
 impl ChannelBuilder {
@@ -175,6 +178,7 @@ impl ChannelBuilder {
         Self {
             uri: uri.into(),
             config: ChannelConfig::default(),
+            endpoints: None,
         }
     }
 
@@ -270,9 +274,20 @@ impl ChannelBuilder {
         self
     }
 
+    /// Balance new subchannels across `endpoints`.
+    ///
+    /// See [`Channel::pick_subchannel`].
+    #[must_use]
+    pub fn endpoint_set(mut self, endpoints: EndpointSet) -> Self {
+        self.endpoints = Some(endpoints);
+        self
+    }
+
     /// Build the channel.
     pub async fn connect(self) -> Result<Channel, GrpcError> {
-        Channel::connect_with_config(&self.uri, self.config).await
+        let mut channel = Channel::connect_with_config(&self.uri, self.config).await?;
+        channel.endpoints = self.endpoints;
+        Ok(channel)
     }
 }
 
@@ -283,6 +298,8 @@ pub struct Channel {
     uri: String,
     /// Channel configuration.
     config: ChannelConfig,
+    /// Balanced endpoints for new subchannels.
+    endpoints: Option<EndpointSet>,
 }
 
 impl Channel {
@@ -296,6 +313,8 @@ impl Channel {
     ///
     /// Supports both in-memory loopback transport (host: `loopback`) and real
     /// HTTP/2 connections to localhost (host: `localhost` or `127.0.0.1`).
+    /// Unix socket targets (`unix:/path`, `unix-abstract:name`) are accepted
+    /// as local endpoints.
     pub async fn connect(uri: impl Into<String>) -> Result<Self, GrpcError> {
         Self::connect_with_config(&uri.into(), ChannelConfig::default()).await
     }
@@ -311,6 +330,7 @@ impl Channel {
         Ok(Self {
             uri: uri.to_string(),
             config,
+            endpoints: None,
         })
     }
 
@@ -325,6 +345,23 @@ impl Channel {
     pub fn config(&self) -> &ChannelConfig {
         &self.config
     }
+
+    /// Get the endpoint set new subchannels are balanced across, if any.
+    #[must_use]
+    pub fn endpoint_set(&self) -> Option<&EndpointSet> {
+        self.endpoints.as_ref()
+    }
+
+    /// Pick the endpoint for a new subchannel.
+    ///
+    /// gRPC balances per subchannel rather than per call: the caller dials the
+    /// leased address, holds the lease for the subchannel's lifetime and
+    /// resolves it with the connection outcome. Returns `None` when the channel
+    /// has no endpoint set or every endpoint is excluded.
+    #[must_use]
+    pub fn pick_subchannel(&self) -> Option<EndpointLease> {
+        self.endpoints.as_ref()?.pick(&[])
+    }
 }
 
 /// A gRPC client for making RPC calls.
@@ -664,10 +701,36 @@ impl<C: Codec> GrpcClient<C> {
     }
 }
 
+/// Returns the socket target of a `unix:` or `unix-abstract:` channel URI.
+///
+/// Follows the gRPC naming convention: `unix:path`, `unix:///absolute/path`,
+/// and `unix-abstract:name`. Unix sockets are local IPC, so they fall inside
+/// the localhost-bounded client transport.
+fn unix_channel_target(uri: &str) -> Option<&str> {
+    if let Some(rest) = uri.strip_prefix("unix-abstract:") {
+        return Some(rest);
+    }
+    let rest = uri.strip_prefix("unix:")?;
+    Some(rest.strip_prefix("//").unwrap_or(rest))
+}
+
 fn validate_channel_uri(uri: &str) -> Result<(), GrpcError> {
     if uri.is_empty() {
         return Err(GrpcError::transport("channel URI cannot be empty"));
     }
+    if let Some(target) = unix_channel_target(uri) {
+        if target.is_empty() {
+            return Err(GrpcError::transport(
+                "unix channel URI is missing a socket path",
+            ));
+        }
+        if target.chars().any(char::is_control) {
+            return Err(GrpcError::transport(
+                "unix channel URI path cannot contain control characters",
+            ));
+        }
+        return Ok(());
+    }
     let (scheme, remainder) = uri
         .split_once("://")
         .ok_or_else(|| GrpcError::transport("channel URI is missing a scheme separator"))?;
@@ -709,9 +772,13 @@ fn validate_channel_uri(uri: &str) -> Result<(), GrpcError> {
 }
 
 fn validate_channel_security(uri: &str, config: &ChannelConfig) -> Result<(), GrpcError> {
-    let (scheme, _) = uri
-        .split_once("://")
-        .ok_or_else(|| GrpcError::transport("channel URI is missing a scheme separator"))?;
+    let scheme = if unix_channel_target(uri).is_some() {
+        "unix"
+    } else {
+        uri.split_once("://")
+            .ok_or_else(|| GrpcError::transport("channel URI is missing a scheme separator"))?
+            .0
+    };
     if scheme.eq_ignore_ascii_case("https") || config.use_tls {
         return Err(GrpcError::transport_kind(
             TransportErrorKind::ProtocolViolation,
@@ -765,15 +832,15 @@ fn enforce_deadline_budget(timeout: Option<Duration>) -> Result<(), Status> {
     Ok(())
 }
 
-/// Remaining time until the ambient [`Cx`](crate::cx::Cx) budget deadline,
-/// read against the ambient timer driver (exact under lab virtual time)
-/// with a wall-clock fallback (br-asupersync-server-stack-hardening-eeexl1.1.3).
+/// Remaining time until the ambient [`Cx`](crate::cx::Cx) effective deadline
+/// (budget or enclosing `timeout()`), read against the ambient timer driver
+/// (exact under lab virtual time) with a wall-clock fallback
+/// (br-asupersync-server-stack-hardening-eeexl1.1.3).
 ///
-/// `None` when no ambient context is installed or its budget carries no
-/// deadline; `Some(Duration::ZERO)` when the deadline has already passed.
+/// `None` when no ambient context is installed or it carries no deadline; `Some(Duration::ZERO)` when the deadline has already passed.
 fn ambient_remaining_budget() -> Option<Duration> {
     crate::cx::Cx::with_current(|cx| {
-        let deadline = cx.budget().deadline?;
+        let deadline = cx.deadline()?;
         let now = cx
             .timer_driver()
             .map_or_else(crate::time::wall_now, |timer| timer.now());
@@ -1817,6 +1884,34 @@ mod tests {
         assert_eq!(cloned.uri(), "http://loopback:8080");
     }
 
+    #[test]
+    fn channel_builder_endpoint_set_leases_subchannels() {
+        use crate::net::{BalancePolicy, EndpointSetConfig};
+        use std::net::SocketAddr;
+
+        let a: SocketAddr = "127.0.0.1:50051".parse().unwrap();
+        let b: SocketAddr = "127.0.0.1:50052".parse().unwrap();
+        let endpoints =
+            EndpointSet::new(EndpointSetConfig::new(BalancePolicy::RoundRobin), [a, b]);
+        let channel = futures_lite::future::block_on(
+            Channel::builder("http://localhost:50051")
+                .endpoint_set(endpoints.clone())
+                .connect(),
+        )
+        .unwrap();
+        assert_eq!(channel.endpoint_set().map(EndpointSet::len), Some(2));
+
+        let first = channel.pick_subchannel().expect("first subchannel");
+        let second = channel.pick_subchannel().expect("second subchannel");
+        assert_ne!(first.addr(), second.addr());
+        first.succeed();
+        second.fail();
+
+        let failures: u64 = endpoints.metrics().iter().map(|m| m.failures).sum();
+        assert_eq!(failures, 1);
+        assert!(make_channel("http://loopback:8080").pick_subchannel().is_none());
+    }
+
     #[test]
     fn channel_uri_accessor() {
         let channel = make_channel("http://loopback:9090");
@@ -2720,6 +2815,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn channel_connect_accepts_unix_socket_targets() {
+        for uri in [
+            "unix:/run/sidecar.sock",
+            "unix:///run/sidecar.sock",
+            "unix:relative.sock",
+            "unix-abstract:sidecar",
+        ] {
+            let channel = futures_lite::future::block_on(Channel::connect(uri))
+                .unwrap_or_else(|err| panic!("unix target should connect: {uri}: {err:?}"));
+            assert_eq!(channel.uri(), uri);
+        }
+
+        for uri in ["unix:", "unix-abstract:", "unix:/run/a\nb.sock"] {
+            let error = futures_lite::future::block_on(Channel::connect(uri))
+                .expect_err(&format!("malformed unix target must fail: {uri:?}"));
+            assert!(matches!(error, GrpcError::Transport(..)), "{error:?}");
+        }
+
+        let tls = futures_lite::future::block_on(
+            Channel::builder("unix:/run/sidecar.sock").tls().connect(),
+        );
+        assert!(tls.is_err(), "TLS over unix target must fail closed");
+    }
+
     #[test]
     fn channel_connect_rejects_userinfo_bypass() {
         // Regression: "loopback" in userinfo must not fool the host check.
@@ -10,6 +10,7 @@ use crate::http::h1::codec::{
     parse_chunk_size_line, parse_header_line, require_transfer_encoding_chunked,
     unique_header_value, validate_header_field,
 };
+use crate::http::h1::stream::ChunkedEncoder;
 use crate::http::h1::types::{Method, Request, Response, Version};
 use crate::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
 use memchr::memmem;
@@ -481,20 +482,7 @@ impl crate::codec::Encoder<Request> for Http1ClientCodec {
     type Error = HttpError;
 
     fn encode(&mut self, req: Request, dst: &mut BytesMut) -> Result<(), HttpError> {
-        if req.uri.contains('\r')
-            || req.uri.contains('\n')
-            || req.uri.contains(' ')
-            || req.uri.contains('\t')
-        {
-            return Err(HttpError::BadRequestLine);
-        }
-        if req.method.as_str().contains('\r')
-            || req.method.as_str().contains('\n')
-            || req.method.as_str().contains(' ')
-            || req.method.as_str().contains('\t')
-        {
-            return Err(HttpError::BadMethod);
-        }
+        validate_request_line(&req)?;
 
         let te = unique_header_value(&req.headers, "Transfer-Encoding")?;
         let cl = unique_header_value(&req.headers, "Content-Length")?;
@@ -549,21 +537,7 @@ impl crate::codec::Encoder<Request> for Http1ClientCodec {
         let headers_bytes: usize = req.headers.iter().map(|(n, v)| n.len() + v.len() + 4).sum();
         dst.reserve(64 + req.uri.len() + headers_bytes + req.body.len());
 
-        // Request line: "GET /path HTTP/1.1\r\n"
-        dst.extend_from_slice(req.method.as_str().as_bytes());
-        dst.extend_from_slice(b" ");
-        dst.extend_from_slice(req.uri.as_bytes());
-        dst.extend_from_slice(b" ");
-        dst.extend_from_slice(req.version.as_str().as_bytes());
-        dst.extend_from_slice(b"\r\n");
-
-        // Headers
-        for (name, value) in &req.headers {
-            dst.extend_from_slice(name.as_bytes());
-            dst.extend_from_slice(b": ");
-            dst.extend_from_slice(value.as_bytes());
-            dst.extend_from_slice(b"\r\n");
-        }
+        write_request_head(&req, dst);
 
         if chunked {
             dst.extend_from_slice(b"\r\n");
@@ -604,6 +578,44 @@ impl crate::codec::Encoder<Request> for Http1ClientCodec {
     }
 }
 
+fn validate_request_line(req: &Request) -> Result<(), HttpError> {
+    if req.uri.contains('\r')
+        || req.uri.contains('\n')
+        || req.uri.contains(' ')
+        || req.uri.contains('\t')
+    {
+        return Err(HttpError::BadRequestLine);
+    }
+    if req.method.as_str().contains('\r')
+        || req.method.as_str().contains('\n')
+        || req.method.as_str().contains(' ')
+        || req.method.as_str().contains('\t')
+    {
+        return Err(HttpError::BadMethod);
+    }
+    Ok(())
+}
+
+/// Writes the request line and header fields, without the blank line that
+/// ends the head. Callers validate the request beforehand.
+fn write_request_head(req: &Request, dst: &mut BytesMut) {
+    // Request line: "GET /path HTTP/1.1\r\n"
+    dst.extend_from_slice(req.method.as_str().as_bytes());
+    dst.extend_from_slice(b" ");
+    dst.extend_from_slice(req.uri.as_bytes());
+    dst.extend_from_slice(b" ");
+    dst.extend_from_slice(req.version.as_str().as_bytes());
+    dst.extend_from_slice(b"\r\n");
+
+    // Headers
+    for (name, value) in &req.headers {
+        dst.extend_from_slice(name.as_bytes());
+        dst.extend_from_slice(b": ");
+        dst.extend_from_slice(value.as_bytes());
+        dst.extend_from_slice(b"\r\n");
+    }
+}
+
 /// A simple HTTP/1.1 client for sending a single request over a transport.
 pub struct Http1Client;
 
@@ -735,7 +747,7 @@ impl Http1Client {
 
         let header_end = find_headers_end(write_buf.as_ref()).ok_or(HttpError::BadRequestLine)?;
         let (head_bytes, body_bytes) = write_buf.as_ref().split_at(header_end);
-        let mut request_body_sent = !expect_continue || body_bytes.is_empty();
+        let request_body_sent = !expect_continue || body_bytes.is_empty();
 
         // With `Expect: 100-continue`, send only the request head first and
         // wait for either an interim 100 or a final response before sending the
@@ -748,104 +760,217 @@ impl Http1Client {
         }
         io.flush().await?;
 
-        // Read response head (status line + headers).
-        let mut read_buf = BytesMut::with_capacity(8192);
-        let mut scratch = [0u8; 8192];
-        let mut informational_responses = 0usize;
-        loop {
-            if let Some(end) = find_headers_end(read_buf.as_ref()) {
-                if end > DEFAULT_MAX_HEADERS_SIZE {
-                    return Err(HttpError::HeadersTooLarge);
-                }
+        let deferred_body = (!request_body_sent).then_some(body_bytes);
+        read_response_head(io, &request_method, max_body_size, deferred_body).await
+    }
 
-                let head_bytes = read_buf.split_to(end);
-                let head_str = std::str::from_utf8(head_bytes.as_ref())
-                    .map_err(|_| HttpError::BadRequestLine)?;
+    /// Send a request whose body is streamed from `body`, and return a
+    /// streaming response.
+    ///
+    /// The body is framed with `Content-Length` when its size hint is exact
+    /// and with `Transfer-Encoding: chunked` otherwise; any framing headers
+    /// already on `req`, and its buffered body, are replaced. The body is
+    /// written in full before the response is read, so `Expect:
+    /// 100-continue` is not honoured. A sized body that yields a different
+    /// number of bytes than it announced fails with
+    /// [`HttpError::BadContentLength`].
+    pub async fn request_with_body<T, B>(
+        io: T,
+        req: Request,
+        body: B,
+    ) -> Result<ClientStreamingResponse<T>, HttpError>
+    where
+        T: AsyncRead + AsyncWrite + Unpin,
+        B: Body + Unpin,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        Self::request_with_body_and_max_body_size(io, req, body, DEFAULT_MAX_BODY_SIZE).await
+    }
 
-                let mut lines = head_str.split("\r\n");
-                let status_line = lines.next().ok_or(HttpError::BadRequestLine)?;
-                let (version, status, reason) = parse_status_line(status_line)?;
+    /// Send a streamed request body with a custom maximum response body
+    /// size.
+    pub async fn request_with_body_and_max_body_size<T, B>(
+        mut io: T,
+        mut req: Request,
+        mut body: B,
+        max_body_size: usize,
+    ) -> Result<ClientStreamingResponse<T>, HttpError>
+    where
+        T: AsyncRead + AsyncWrite + Unpin,
+        B: Body + Unpin,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        if req.version == Version::Http10 && body.size_hint().exact().is_none() {
+            return Err(HttpError::BadTransferEncoding);
+        }
+        validate_request_line(&req)?;
+        req.headers.retain(|(name, _)| {
+            !name.eq_ignore_ascii_case("content-length")
+                && !name.eq_ignore_ascii_case("transfer-encoding")
+                && !name.eq_ignore_ascii_case("expect")
+        });
+        req.body.clear();
+        req.trailers.clear();
+        let declared = body.size_hint().exact();
+        if let Some(len) = declared {
+            req.headers.push(("Content-Length".to_owned(), len.to_string()));
+        } else {
+            req.headers.push(("Transfer-Encoding".to_owned(), "chunked".to_owned()));
+        }
+        for (name, value) in &req.headers {
+            validate_header_field(name, value)?;
+        }
 
-                let mut headers = Vec::new();
-                for line in lines {
-                    if line.is_empty() {
-                        break;
-                    }
-                    headers.push(parse_header_line(line)?);
-                    if headers.len() > MAX_HEADERS {
-                        return Err(HttpError::TooManyHeaders);
+        let mut out = BytesMut::with_capacity(1024);
+        write_request_head(&req, &mut out);
+        out.extend_from_slice(b"\r\n");
+        io.write_all(out.as_ref()).await?;
+
+        let mut chunked = declared.is_none().then(ChunkedEncoder::new);
+        let mut sent = 0_u64;
+        while let Some(frame) = poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+            let frame = frame.map_err(|err| HttpError::Io(std::io::Error::other(err)))?;
+            out.clear();
+            match (frame, chunked.as_mut()) {
+                (frame, Some(encoder)) => encoder.encode_frame(frame, &mut out),
+                (Frame::Data(mut data), None) => {
+                    while data.has_remaining() {
+                        let chunk = data.chunk();
+                        out.extend_from_slice(chunk);
+                        data.advance(chunk.len());
                     }
                 }
+                // Trailers cannot be carried by a Content-Length body.
+                (Frame::Trailers(_), None) => {}
+            }
+            sent = sent.saturating_add(out.len() as u64);
+            if declared.is_some_and(|len| sent > len) {
+                return Err(HttpError::BadContentLength);
+            }
+            io.write_all(out.as_ref()).await?;
+        }
+        if let Some(encoder) = chunked.as_mut() {
+            out.clear();
+            encoder.finalize(None, &mut out);
+            io.write_all(out.as_ref()).await?;
+        } else if declared.is_some_and(|len| sent != len) {
+            return Err(HttpError::BadContentLength);
+        }
+        io.flush().await?;
 
-                // RFC 9110: 1xx are informational responses. Keep reading
-                // until a final response is received, except 101 which is
-                // terminal. `100 Continue` is the signal that allows a deferred
-                // request body to be sent.
-                if (100..=199).contains(&status) && status != 101 {
-                    informational_responses += 1;
-                    if informational_responses > MAX_INFORMATIONAL_RESPONSES {
-                        return Err(HttpError::TooManyInformationalResponses {
-                            actual: informational_responses,
-                            limit: MAX_INFORMATIONAL_RESPONSES,
-                        });
-                    }
-                    if status == 100 && !request_body_sent {
-                        io.write_all(body_bytes).await?;
-                        io.flush().await?;
-                        request_body_sent = true;
-                    }
-                    continue;
-                }
+        read_response_head(io, &req.method, max_body_size, None).await
+    }
+}
 
-                let kind = response_body_kind(&headers, status, &request_method, max_body_size)?;
-
-                let head = crate::http::h1::stream::ResponseHead {
-                    version,
-                    status,
-                    reason,
-                    headers,
-                };
-
-                // Preserve any already-buffered bytes even for empty-body
-                // responses. On protocol upgrades (101), these bytes belong to
-                // the upgraded stream.
-                let body_buf = read_buf;
-
-                let body =
-                    ClientIncomingBody::with_max_body_size(io, kind, body_buf, max_body_size);
-                // `request_body_sent` is false only when an `Expect: 100-continue`
-                // body was withheld because this final response arrived without a
-                // preceding `100 Continue` (br-asupersync-h1-expect-100-pool-h9le7v).
-                return Ok(ClientStreamingResponse {
-                    head,
-                    body,
-                    body_withheld: !request_body_sent,
-                });
+/// Reads the response head (skipping informational responses) and wraps the
+/// transport in a streaming body reader.
+///
+/// `deferred_body` holds request body bytes withheld for
+/// `Expect: 100-continue`; they are written when a `100 Continue` arrives.
+async fn read_response_head<T>(
+    mut io: T,
+    request_method: &Method,
+    max_body_size: usize,
+    mut deferred_body: Option<&[u8]>,
+) -> Result<ClientStreamingResponse<T>, HttpError>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    // Read response head (status line + headers).
+    let mut read_buf = BytesMut::with_capacity(8192);
+    let mut scratch = [0u8; 8192];
+    let mut informational_responses = 0usize;
+    loop {
+        if let Some(end) = find_headers_end(read_buf.as_ref()) {
+            if end > DEFAULT_MAX_HEADERS_SIZE {
+                return Err(HttpError::HeadersTooLarge);
             }
 
-            if read_buf.len() > DEFAULT_MAX_HEADERS_SIZE {
-                return Err(HttpError::HeadersTooLarge);
+            let head_bytes = read_buf.split_to(end);
+            let head_str = std::str::from_utf8(head_bytes.as_ref())
+                .map_err(|_| HttpError::BadRequestLine)?;
+
+            let mut lines = head_str.split("\r\n");
+            let status_line = lines.next().ok_or(HttpError::BadRequestLine)?;
+            let (version, status, reason) = parse_status_line(status_line)?;
+
+            let mut headers = Vec::new();
+            for line in lines {
+                if line.is_empty() {
+                    break;
+                }
+                headers.push(parse_header_line(line)?);
+                if headers.len() > MAX_HEADERS {
+                    return Err(HttpError::TooManyHeaders);
+                }
             }
 
-            let n = poll_fn(|cx| {
-                let mut rb = ReadBuf::new(&mut scratch);
-                match Pin::new(&mut io).poll_read(cx, &mut rb) {
-                    Poll::Pending => Poll::Pending,
-                    Poll::Ready(Ok(())) => Poll::Ready(Ok(rb.filled().len())),
-                    Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            // RFC 9110: 1xx are informational responses. Keep reading
+            // until a final response is received, except 101 which is
+            // terminal. `100 Continue` is the signal that allows a deferred
+            // request body to be sent.
+            if (100..=199).contains(&status) && status != 101 {
+                informational_responses += 1;
+                if informational_responses > MAX_INFORMATIONAL_RESPONSES {
+                    return Err(HttpError::TooManyInformationalResponses {
+                        actual: informational_responses,
+                        limit: MAX_INFORMATIONAL_RESPONSES,
+                    });
+                }
+                if status == 100 && let Some(body_bytes) = deferred_body.take() {
+                    io.write_all(body_bytes).await?;
+                    io.flush().await?;
                 }
-            })
-            .await?;
-
-            if n == 0 {
-                return Err(HttpError::Io(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    "connection closed before response headers",
-                )));
+                continue;
             }
 
-            read_buf.extend_from_slice(&scratch[..n]);
+            let kind = response_body_kind(&headers, status, request_method, max_body_size)?;
+
+            let head = crate::http::h1::stream::ResponseHead {
+                version,
+                status,
+                reason,
+                headers,
+            };
+
+            // Preserve any already-buffered bytes even for empty-body
+            // responses. On protocol upgrades (101), these bytes belong to
+            // the upgraded stream.
+            let body_buf = read_buf;
+
+            let body = ClientIncomingBody::with_max_body_size(io, kind, body_buf, max_body_size);
+            // `deferred_body` is still set only when an `Expect: 100-continue`
+            // body was withheld because this final response arrived without a
+            // preceding `100 Continue` (br-asupersync-h1-expect-100-pool-h9le7v).
+            return Ok(ClientStreamingResponse {
+                head,
+                body,
+                body_withheld: deferred_body.is_some(),
+            });
+        }
+
+        if read_buf.len() > DEFAULT_MAX_HEADERS_SIZE {
+            return Err(HttpError::HeadersTooLarge);
         }
+
+        let n = poll_fn(|cx| {
+            let mut rb = ReadBuf::new(&mut scratch);
+            match Pin::new(&mut io).poll_read(cx, &mut rb) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(Ok(())) => Poll::Ready(Ok(rb.filled().len())),
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            }
+        })
+        .await?;
+
+        if n == 0 {
+            return Err(HttpError::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "connection closed before response headers",
+            )));
+        }
+
+        read_buf.extend_from_slice(&scratch[..n]);
     }
 }
 
@@ -1840,6 +1965,91 @@ mod tests {
         assert!(request_bytes.contains("Host: example.com\r\n"));
     }
 
+    #[test]
+    fn request_with_body_replaces_framing_with_exact_length() {
+        let response_bytes = b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n";
+        let io = TestIo::new(response_bytes);
+
+        let req = Request {
+            method: Method::Put,
+            uri: "/upload".to_string(),
+            version: Version::Http11,
+            headers: vec![
+                ("Host".to_string(), "example.com".to_string()),
+                ("Content-Length".to_string(), "99".to_string()),
+            ],
+            body: b"stale".to_vec(),
+            trailers: Vec::new(),
+            peer_addr: None,
+        };
+        let data = crate::bytes::Bytes::from(b"hello".to_vec());
+        let body = crate::http::body::Full::new(BytesCursor::new(data));
+
+        let resp = block_on(Http1Client::request_with_body(io, req, body)).expect("response");
+        assert_eq!(resp.head.status, 201);
+        let io = resp.body.into_inner();
+        let request_bytes = String::from_utf8(io.written).expect("request write should be utf8");
+        assert!(request_bytes.starts_with("PUT /upload HTTP/1.1\r\n"));
+        assert!(request_bytes.contains("Content-Length: 5\r\n"));
+        assert!(!request_bytes.contains("99"));
+        assert!(request_bytes.ends_with("\r\n\r\nhello"));
+    }
+
+    #[test]
+    fn request_with_body_chunks_unsized_multipart() {
+        let response_bytes = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+        let io = TestIo::new(response_bytes);
+
+        let body = crate::http::multipart::MultipartBody::builder()
+            .boundary("xyz")
+            .expect("valid boundary")
+            .reader("f", "f.bin", "application/octet-stream", &b"data"[..], None)
+            .build()
+            .expect("build");
+        let req = Request {
+            method: Method::Post,
+            uri: "/form".to_string(),
+            version: Version::Http11,
+            headers: vec![
+                ("Host".to_string(), "example.com".to_string()),
+                ("Content-Type".to_string(), body.content_type_header()),
+            ],
+            body: Vec::new(),
+            trailers: Vec::new(),
+            peer_addr: None,
+        };
+
+        let resp = block_on(Http1Client::request_with_body(io, req, body)).expect("response");
+        assert_eq!(resp.head.status, 200);
+        let io = resp.body.into_inner();
+        let request_bytes = String::from_utf8(io.written).expect("request write should be utf8");
+        assert!(request_bytes.contains("Transfer-Encoding: chunked\r\n"));
+        assert!(!request_bytes.contains("Content-Length"));
+        assert!(request_bytes.contains("data\r\n--xyz--\r\n"));
+        assert!(request_bytes.ends_with("\r\n0\r\n\r\n"));
+    }
+
+    #[test]
+    fn request_with_body_rejects_unsized_body_on_http10() {
+        let io = TestIo::new(b"");
+        let body = crate::http::multipart::MultipartBody::builder()
+            .reader("f", "f.bin", "application/octet-stream", &b"data"[..], None)
+            .build()
+            .expect("build");
+        let req = Request {
+            method: Method::Post,
+            uri: "/form".to_string(),
+            version: Version::Http10,
+            headers: Vec::new(),
+            body: Vec::new(),
+            trailers: Vec::new(),
+            peer_addr: None,
+        };
+
+        let err = block_on(Http1Client::request_with_body(io, req, body)).expect_err("http/1.0");
+        assert!(matches!(err, HttpError::BadTransferEncoding));
+    }
+
     #[test]
     fn request_with_io_rejects_unread_prefetched_bytes() {
         let response_bytes =
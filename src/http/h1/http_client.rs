@@ -42,9 +42,13 @@ use crate::http::h1::client::{ClientStreamingResponse, Http1Client};
 use crate::http::h1::types::{
     Method, MultipartForm, Request, Response, Version, url_encode_params,
 };
+use crate::http::multipart::MultipartBody;
 use crate::http::pool::{Pool, PoolConfig, PoolKey};
 use crate::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use crate::net::endpoint_set::EndpointSet;
 use crate::net::tcp::stream::TcpStream;
+#[cfg(unix)]
+use crate::net::unix::UnixStream;
 #[cfg(feature = "tls")]
 use crate::tls::{TlsConnectorBuilder, TlsStream};
 use crate::types::Time;
@@ -172,6 +176,87 @@ impl ClientError {
     pub fn is_cancelled(&self) -> bool {
         matches!(self, Self::Cancelled)
     }
+
+    /// The phase of the exchange the error arose in.
+    ///
+    /// Returns `None` for errors not tied to one attempt: invalid URLs,
+    /// redirect limits, cancellation, and an elapsed request deadline.
+    #[must_use]
+    pub const fn phase(&self) -> Option<RequestPhase> {
+        match self {
+            Self::DnsError(_) => Some(RequestPhase::Resolve),
+            Self::ConnectError(_)
+            | Self::ConnectTunnelRefused { .. }
+            | Self::InvalidConnectInput(_)
+            | Self::ProxyError(_)
+            | Self::PoolExhausted { .. } => Some(RequestPhase::Connect),
+            Self::TlsError(_) => Some(RequestPhase::Handshake),
+            Self::HttpError(_) | Self::Io(_) => Some(RequestPhase::Request),
+            Self::InvalidUrl(_)
+            | Self::TooManyRedirects { .. }
+            | Self::Cancelled
+            | Self::DeadlineExceeded => None,
+        }
+    }
+
+    /// The resilience mechanism a failed attempt of `method` engages.
+    ///
+    /// Resolve failures re-resolve the name without touching endpoint health.
+    /// Connect and handshake failures never reached the server, so they count
+    /// against the endpoint and move to another one. A request-phase failure
+    /// moves on only for safe methods whose transport was dropped.
+    #[must_use]
+    pub fn retry_class(&self, method: &Method) -> RetryClass {
+        match self {
+            Self::DnsError(_) => RetryClass::ReResolve,
+            Self::ConnectError(_) | Self::TlsError(_) | Self::PoolExhausted { .. } => {
+                RetryClass::NextEndpoint
+            }
+            _ if method_is_safe_to_retry_after_stale_reuse(method)
+                && client_error_looks_like_stale_reuse(self) =>
+            {
+                RetryClass::NextEndpoint
+            }
+            _ => RetryClass::Fail,
+        }
+    }
+}
+
+/// Phase of an HTTP exchange, see [`ClientError::phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestPhase {
+    /// Resolving the host name.
+    Resolve,
+    /// Opening the transport, including proxy negotiation.
+    Connect,
+    /// Negotiating TLS.
+    Handshake,
+    /// Writing the request or reading the response.
+    Request,
+}
+
+impl RequestPhase {
+    /// Stable lowercase name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Resolve => "resolve",
+            Self::Connect => "connect",
+            Self::Handshake => "handshake",
+            Self::Request => "request",
+        }
+    }
+}
+
+/// What a failed attempt does next, see [`ClientError::retry_class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryClass {
+    /// Resolve the name again; endpoint health is untouched.
+    ReResolve,
+    /// Record the failure against the endpoint and try a different one.
+    NextEndpoint,
+    /// Surface the error to the caller.
+    Fail,
 }
 
 /// Check if the Cx has been cancelled and return `ClientError::Cancelled` if so.
@@ -185,7 +270,8 @@ fn check_cx(cx: &Cx) -> Result<(), ClientError> {
 
 /// Drives an outbound exchange under the effective total deadline
 /// (br-asupersync-server-stack-hardening-eeexl1.1.3): the **meet** of the
-/// remaining `cx` budget, the client's configured total request timeout,
+/// remaining time before the effective `cx` deadline (budget or enclosing
+/// `timeout()`), the client's configured total request timeout,
 /// and any per-call override — the tightest bound wins, so neither config
 /// nor caller can extend past the caller's budget deadline.
 ///
@@ -208,8 +294,7 @@ async fn drive_with_budget_deadline<T>(
         .or_else(|| Cx::with_current(|ambient| ambient.timer_driver()).flatten())
         .map_or_else(crate::time::wall_now, |timer| timer.now());
     let remaining = cx
-        .budget()
-        .deadline
+        .deadline()
         .map(|deadline| std::time::Duration::from_nanos(deadline.duration_since(now)));
     let effective = [remaining, configured, per_call]
         .into_iter()
@@ -270,7 +355,7 @@ pub enum Scheme {
     Https,
 }
 
-/// HTTP client transport stream (plain TCP or TLS).
+/// HTTP client transport stream (plain TCP, TLS, or a Unix domain socket).
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum ClientIo {
@@ -282,6 +367,9 @@ pub enum ClientIo {
     /// TLS over an HTTP CONNECT tunnel.
     #[cfg(feature = "tls")]
     TlsTunnel(Box<TlsStream<HttpConnectTunnel<Self>>>),
+    /// Plain Unix domain socket stream.
+    #[cfg(unix)]
+    Unix(UnixStream),
 }
 
 impl AsyncRead for ClientIo {
@@ -296,6 +384,8 @@ impl AsyncRead for ClientIo {
             Self::Tls(s) => Pin::new(s).poll_read(cx, buf),
             #[cfg(feature = "tls")]
             Self::TlsTunnel(s) => Pin::new(s.as_mut()).poll_read(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(s) => Pin::new(s).poll_read(cx, buf),
         }
     }
 }
@@ -312,6 +402,8 @@ impl AsyncWrite for ClientIo {
             Self::Tls(s) => Pin::new(s).poll_write(cx, data),
             #[cfg(feature = "tls")]
             Self::TlsTunnel(s) => Pin::new(s.as_mut()).poll_write(cx, data),
+            #[cfg(unix)]
+            Self::Unix(s) => Pin::new(s).poll_write(cx, data),
         }
     }
 
@@ -322,6 +414,8 @@ impl AsyncWrite for ClientIo {
             Self::Tls(s) => Pin::new(s).poll_flush(cx),
             #[cfg(feature = "tls")]
             Self::TlsTunnel(s) => Pin::new(s.as_mut()).poll_flush(cx),
+            #[cfg(unix)]
+            Self::Unix(s) => Pin::new(s).poll_flush(cx),
         }
     }
 
@@ -332,6 +426,8 @@ impl AsyncWrite for ClientIo {
             Self::Tls(s) => Pin::new(s).poll_shutdown(cx),
             #[cfg(feature = "tls")]
             Self::TlsTunnel(s) => Pin::new(s.as_mut()).poll_shutdown(cx),
+            #[cfg(unix)]
+            Self::Unix(s) => Pin::new(s).poll_shutdown(cx),
         }
     }
 }
@@ -819,6 +915,35 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Routes every connection over the Unix domain socket at `path`.
+    ///
+    /// The request URL still supplies the `Host` header, request target, and
+    /// pool key; only the transport is replaced. Proxy settings are ignored
+    /// and `https://` URLs are rejected while a socket path is configured.
+    #[cfg(unix)]
+    #[must_use]
+    pub fn unix_socket(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.config.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Balances requests for `authority` (`host:port`) across `endpoints`.
+    ///
+    /// Each request picks an endpoint under the set's policy, connections are
+    /// pooled per endpoint, and a request that fails before producing a
+    /// response moves to a different endpoint while the set's
+    /// `max_attempts` allows it. Registering the same authority again
+    /// replaces its set.
+    #[must_use]
+    pub fn endpoint_set(mut self, authority: impl Into<String>, endpoints: EndpointSet) -> Self {
+        let authority = authority.into();
+        self.config
+            .endpoint_sets
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&authority));
+        self.config.endpoint_sets.push((authority, endpoints));
+        self
+    }
+
     /// Sets a custom time source for deterministic pool timestamps.
     #[must_use]
     pub fn with_time_getter(mut self, time_getter: fn() -> Time) -> Self {
@@ -863,6 +988,18 @@ pub struct HttpClientConfig {
     /// caller can extend past the caller's budget deadline. `None` (the
     /// default) imposes no client-side total timeout.
     pub request_timeout: Option<std::time::Duration>,
+    /// Unix domain socket used as the transport for every connection.
+    ///
+    /// When set, TCP dialing and proxy routing are bypassed.
+    #[cfg(unix)]
+    pub unix_socket: Option<std::path::PathBuf>,
+    /// Endpoint sets keyed by `host:port` authority.
+    ///
+    /// Requests to a listed authority are balanced across its set instead of
+    /// dialing the address DNS returns first. The URL still supplies the
+    /// `Host` header and TLS server name. Proxy and Unix socket transports
+    /// take precedence.
+    pub endpoint_sets: Vec<(String, EndpointSet)>,
     /// Time source used for pool bookkeeping.
     time_getter: fn() -> Time,
 }
@@ -879,6 +1016,9 @@ impl Default for HttpClientConfig {
             proxy_url: None,
             max_body_size: None,
             request_timeout: None,
+            #[cfg(unix)]
+            unix_socket: None,
+            endpoint_sets: Vec::new(),
             time_getter: wall_clock_now,
         }
     }
@@ -1221,6 +1361,17 @@ impl HttpClient {
             .await
     }
 
+    /// Send a POST multipart request with a streamed body.
+    pub async fn post_multipart_body(
+        &self,
+        cx: &Cx,
+        url: &str,
+        body: MultipartBody,
+    ) -> Result<ClientStreamingResponse<ClientIo>, ClientError> {
+        self.request_multipart_body(cx, Method::Post, url, Vec::new(), body)
+            .await
+    }
+
     /// Send a POST multipart form-data request and stream the response body.
     pub async fn post_multipart_streaming(
         &self,
@@ -1342,6 +1493,31 @@ impl HttpClient {
             .await
     }
 
+    /// Send a multipart request whose parts are streamed from `body`, and
+    /// stream the response body.
+    ///
+    /// The body can only be sent once, so the request is never retried and
+    /// redirects are returned to the caller instead of being followed.
+    pub async fn request_multipart_body(
+        &self,
+        cx: &Cx,
+        method: Method,
+        url: &str,
+        mut extra_headers: Vec<(String, String)>,
+        body: MultipartBody,
+    ) -> Result<ClientStreamingResponse<ClientIo>, ClientError> {
+        check_cx(cx)?;
+        let parsed = ParsedUrl::parse(url)?;
+        if !extra_headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        {
+            extra_headers.push(("Content-Type".to_owned(), body.content_type_header()));
+        }
+        let fut = self.execute_single_with_body(cx, &method, &parsed, &extra_headers, body);
+        drive_with_budget_deadline(cx, self.config.request_timeout, None, fut).await
+    }
+
     /// Establish an HTTP/1.1 CONNECT tunnel through a proxy endpoint.
     ///
     /// `proxy_url` is the proxy server URL (e.g. `http://proxy.local:3128`).
@@ -1356,7 +1532,7 @@ impl HttpClient {
     ) -> Result<HttpConnectTunnel<ClientIo>, ClientError> {
         check_cx(cx)?;
         let proxy = ParsedUrl::parse(proxy_url)?;
-        let io = self.connect_io(cx, &proxy).await?;
+        let io = self.connect_io(cx, &proxy, None).await?;
         establish_http_connect_tunnel(
             io,
             target_authority,
@@ -1556,17 +1732,97 @@ impl HttpClient {
         body: &[u8],
     ) -> Result<Response, ClientError> {
         check_cx(cx)?;
-        if let Some(proxy_url) = self.config.proxy_url.as_deref() {
+        if let Some(proxy_url) = self.active_proxy_url() {
             return self
                 .execute_single_with_proxy(cx, method, parsed, extra_headers, body, proxy_url)
                 .await;
         }
+        if let Some(endpoints) = self.endpoint_set_for(parsed) {
+            return self
+                .execute_balanced(cx, method, parsed, extra_headers, body, endpoints)
+                .await;
+        }
+        self.execute_on_endpoint(cx, method, parsed, extra_headers, body, None)
+            .await
+    }
 
+    /// Executes a request against a balanced endpoint set.
+    ///
+    /// Each attempt holds a lease on one endpoint; the outcome feeds the set's
+    /// health tracking (5xx responses count as endpoint failures). Failures
+    /// are handled by their [`RetryClass`]: those that happened before the
+    /// request could have been observed move to a different endpoint while
+    /// the set's attempt budget allows it.
+    async fn execute_balanced(
+        &self,
+        cx: &Cx,
+        method: &Method,
+        parsed: &ParsedUrl,
+        extra_headers: &[(String, String)],
+        body: &[u8],
+        endpoints: &EndpointSet,
+    ) -> Result<Response, ClientError> {
+        let mut tried = Vec::new();
+        let mut last_error = None;
+        while tried.len() < endpoints.max_attempts() as usize {
+            check_cx(cx)?;
+            let Some(lease) = endpoints.pick(&tried) else {
+                break;
+            };
+            tried.push(lease.addr());
+            let result = self
+                .execute_on_endpoint(cx, method, parsed, extra_headers, body, Some(lease.addr()))
+                .await;
+            match result {
+                Ok(response) => {
+                    if response.status >= 500 {
+                        lease.fail();
+                    } else {
+                        lease.succeed();
+                    }
+                    return Ok(response);
+                }
+                Err(err) if err.is_cancelled() || matches!(err, ClientError::DeadlineExceeded) => {
+                    return Err(err);
+                }
+                Err(err) => {
+                    let class = err.retry_class(method);
+                    if class == RetryClass::ReResolve {
+                        // The endpoint was never reached; leave its health alone.
+                        return Err(err);
+                    }
+                    lease.fail();
+                    if class == RetryClass::Fail {
+                        return Err(err);
+                    }
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ClientError::ConnectError(io::Error::new(
+                io::ErrorKind::NotConnected,
+                format!("no endpoints available for {}", parsed.connect_authority()),
+            ))
+        }))
+    }
+
+    /// Executes a single request over a connection to `endpoint`, or to the
+    /// URL host when no endpoint is given.
+    async fn execute_on_endpoint(
+        &self,
+        cx: &Cx,
+        method: &Method,
+        parsed: &ParsedUrl,
+        extra_headers: &[(String, String)],
+        body: &[u8],
+        endpoint: Option<SocketAddr>,
+    ) -> Result<Response, ClientError> {
         let req = self.build_request(method, parsed, extra_headers, body, None, None);
         let request_forbids_reuse = request_forbids_connection_reuse(&req.headers);
 
-        let key = parsed.pool_key();
-        let acquired = self.acquire_connection(cx, parsed).await?;
+        let key = endpoint_pool_key(parsed, endpoint);
+        let acquired = self.acquire_connection(cx, parsed, endpoint).await?;
         let mut guard = ConnectionGuard::new(self, key.clone(), acquired.pool_id);
         let reused_connection = !acquired.fresh;
 
@@ -1613,6 +1869,7 @@ impl HttpClient {
                             parsed,
                             extra_headers,
                             body,
+                            endpoint,
                         )
                         .await;
                 }
@@ -1629,11 +1886,12 @@ impl HttpClient {
         parsed: &ParsedUrl,
         extra_headers: &[(String, String)],
         body: &[u8],
+        endpoint: Option<SocketAddr>,
     ) -> Result<Response, ClientError> {
         let req = self.build_request(method, parsed, extra_headers, body, None, None);
         let request_forbids_reuse = request_forbids_connection_reuse(&req.headers);
-        let key = parsed.pool_key();
-        let acquired = self.acquire_connection(cx, parsed).await?;
+        let key = endpoint_pool_key(parsed, endpoint);
+        let acquired = self.acquire_connection(cx, parsed, endpoint).await?;
         let mut guard = ConnectionGuard::new(self, key.clone(), acquired.pool_id);
 
         check_cx(cx)?;
@@ -1676,7 +1934,7 @@ impl HttpClient {
         body: &[u8],
     ) -> Result<ClientStreamingResponse<ClientIo>, ClientError> {
         check_cx(cx)?;
-        if let Some(proxy_url) = self.config.proxy_url.as_deref() {
+        if let Some(proxy_url) = self.active_proxy_url() {
             return self
                 .execute_single_streaming_with_proxy(
                     cx,
@@ -1691,12 +1949,29 @@ impl HttpClient {
 
         let req = self.build_request(method, parsed, extra_headers, body, None, None);
 
-        let stream = self.connect_io(cx, parsed).await?;
+        // Streaming responses hold their connection for the body's lifetime,
+        // so the lease covers the exchange up to the response head.
+        let lease = self
+            .endpoint_set_for(parsed)
+            .and_then(|endpoints| endpoints.pick(&[]));
+        let endpoint = lease.as_ref().map(crate::net::EndpointLease::addr);
+        let stream = self.connect_io(cx, parsed, endpoint).await?;
         check_cx(cx)?;
-        let resp = if let Some(max_body_size) = self.config.max_body_size {
-            Http1Client::request_streaming_with_max_body_size(stream, req, max_body_size).await?
+        let result = if let Some(max_body_size) = self.config.max_body_size {
+            Http1Client::request_streaming_with_max_body_size(stream, req, max_body_size).await
         } else {
-            Http1Client::request_streaming(stream, req).await?
+            Http1Client::request_streaming(stream, req).await
+        };
+        let resp = match (result, lease) {
+            (Ok(resp), Some(lease)) => {
+                lease.succeed();
+                resp
+            }
+            (Err(err), Some(lease)) => {
+                lease.fail();
+                return Err(err.into());
+            }
+            (result, None) => result?,
         };
         check_cx(cx)?;
         self.store_response_cookies(&parsed.host, &resp.head.headers);
@@ -1778,6 +2053,79 @@ impl HttpClient {
         Ok(resp)
     }
 
+    /// Execute a single request with a streamed body: no redirects, no
+    /// retries.
+    async fn execute_single_with_body(
+        &self,
+        cx: &Cx,
+        method: &Method,
+        parsed: &ParsedUrl,
+        extra_headers: &[(String, String)],
+        body: MultipartBody,
+    ) -> Result<ClientStreamingResponse<ClientIo>, ClientError> {
+        check_cx(cx)?;
+        let max_body_size = self.config.max_body_size;
+        if let Some(proxy_url) = self.active_proxy_url() {
+            let proxy = parse_proxy_endpoint(proxy_url)?;
+            let proxy_conn = self.connect_via_proxy(cx, parsed, &proxy).await?;
+            check_cx(cx)?;
+            let request_target = if proxy_conn.use_absolute_form {
+                Some(absolute_request_target(parsed))
+            } else {
+                None
+            };
+            let req = self.build_request(
+                method,
+                parsed,
+                extra_headers,
+                &[],
+                request_target,
+                proxy_conn.proxy_authorization.as_deref(),
+            );
+            let resp = if let Some(max_body_size) = max_body_size {
+                Http1Client::request_with_body_and_max_body_size(
+                    proxy_conn.io,
+                    req,
+                    body,
+                    max_body_size,
+                )
+                .await?
+            } else {
+                Http1Client::request_with_body(proxy_conn.io, req, body).await?
+            };
+            check_cx(cx)?;
+            self.store_response_cookies(&parsed.host, &resp.head.headers);
+            return Ok(resp);
+        }
+
+        let req = self.build_request(method, parsed, extra_headers, &[], None, None);
+        let lease = self
+            .endpoint_set_for(parsed)
+            .and_then(|endpoints| endpoints.pick(&[]));
+        let endpoint = lease.as_ref().map(crate::net::EndpointLease::addr);
+        let stream = self.connect_io(cx, parsed, endpoint).await?;
+        check_cx(cx)?;
+        let result = if let Some(max_body_size) = max_body_size {
+            Http1Client::request_with_body_and_max_body_size(stream, req, body, max_body_size).await
+        } else {
+            Http1Client::request_with_body(stream, req, body).await
+        };
+        let resp = match (result, lease) {
+            (Ok(resp), Some(lease)) => {
+                lease.succeed();
+                resp
+            }
+            (Err(err), Some(lease)) => {
+                lease.fail();
+                return Err(err.into());
+            }
+            (result, None) => result?,
+        };
+        check_cx(cx)?;
+        self.store_response_cookies(&parsed.host, &resp.head.headers);
+        Ok(resp)
+    }
+
     async fn connect_via_proxy(
         &self,
         cx: &Cx,
@@ -1800,7 +2148,7 @@ impl HttpClient {
                     port: proxy.port,
                     path: "/".to_owned(),
                 };
-                let proxy_io = self.connect_io(cx, &proxy_parsed).await?;
+                let proxy_io = self.connect_io(cx, &proxy_parsed, None).await?;
 
                 if parsed.scheme == Scheme::Http {
                     return Ok(ProxyConnection {
@@ -2013,9 +2361,52 @@ impl HttpClient {
             .map_err(|e| ClientError::TlsError(e.to_string()))
     }
 
-    async fn connect_io(&self, cx: &Cx, parsed: &ParsedUrl) -> Result<ClientIo, ClientError> {
+    /// Proxy URL in effect for outbound requests.
+    ///
+    /// A configured Unix socket transport takes precedence over any proxy.
+    fn active_proxy_url(&self) -> Option<&str> {
+        #[cfg(unix)]
+        if self.config.unix_socket.is_some() {
+            return None;
+        }
+        self.config.proxy_url.as_deref()
+    }
+
+    fn endpoint_set_for(&self, parsed: &ParsedUrl) -> Option<&EndpointSet> {
+        #[cfg(unix)]
+        if self.config.unix_socket.is_some() {
+            return None;
+        }
+        let authority = parsed.connect_authority();
+        self.config
+            .endpoint_sets
+            .iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(&authority))
+            .map(|(_, endpoints)| endpoints)
+    }
+
+    async fn connect_io(
+        &self,
+        cx: &Cx,
+        parsed: &ParsedUrl,
+        endpoint: Option<SocketAddr>,
+    ) -> Result<ClientIo, ClientError> {
         check_cx(cx)?;
-        let stream = if let Some(socket_addr) = parsed_numeric_socket_addr(parsed) {
+        #[cfg(unix)]
+        if let Some(path) = self.config.unix_socket.as_deref() {
+            if parsed.scheme == Scheme::Https {
+                return Err(ClientError::TlsError(
+                    "TLS over a Unix socket transport is not supported".into(),
+                ));
+            }
+            let stream = UnixStream::connect(path)
+                .await
+                .map_err(ClientError::ConnectError)?;
+            check_cx(cx)?;
+            return Ok(ClientIo::Unix(stream));
+        }
+        let socket_addr = endpoint.or_else(|| parsed_numeric_socket_addr(parsed));
+        let stream = if let Some(socket_addr) = socket_addr {
             TcpStream::connect_socket_addr(socket_addr)
                 .await
                 .map_err(ClientError::ConnectError)?
@@ -2055,6 +2446,7 @@ impl HttpClient {
         &self,
         cx: &Cx,
         parsed: &ParsedUrl,
+        endpoint: Option<SocketAddr>,
     ) -> Result<AcquiredConnection, ClientError> {
         struct ConnectGuard<'a> {
             client: &'a HttpClient,
@@ -2069,7 +2461,7 @@ impl HttpClient {
             }
         }
 
-        let key = parsed.pool_key();
+        let key = endpoint_pool_key(parsed, endpoint);
         let now = self.pool_now();
         self.cleanup_expired_idle_connections(now);
 
@@ -2114,7 +2506,7 @@ impl HttpClient {
             });
         }
 
-        let io = self.connect_io(cx, parsed).await?;
+        let io = self.connect_io(cx, parsed, endpoint).await?;
 
         guard.id = None; // defuse the guard upon success
 
@@ -2756,6 +3148,14 @@ fn request_forbids_connection_reuse(headers: &[(String, String)]) -> bool {
             .any(|(name, _)| name.eq_ignore_ascii_case("upgrade"))
 }
 
+fn endpoint_pool_key(parsed: &ParsedUrl, endpoint: Option<SocketAddr>) -> PoolKey {
+    let key = parsed.pool_key();
+    match endpoint {
+        Some(endpoint) => key.with_endpoint(endpoint),
+        None => key,
+    }
+}
+
 fn method_is_safe_to_retry_after_stale_reuse(method: &Method) -> bool {
     matches!(
         method,
@@ -5026,7 +5426,7 @@ mod tests {
         client.pool.lock().register_connecting(key, Time::ZERO, 1);
 
         let cx = Cx::for_testing();
-        let err = match block_on(client.acquire_connection(&cx, &parsed)) {
+        let err = match block_on(client.acquire_connection(&cx, &parsed, None)) {
             Ok(_) => panic!("pool exhaustion must reject before dialing"),
             Err(err) => err,
         };
@@ -5051,6 +5451,53 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_transport_round_trips_request_and_bypasses_proxy() {
+        use std::io::{Read, Write};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("sidecar.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path).expect("bind uds");
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept uds");
+            let mut buf = [0_u8; 1024];
+            let mut request = Vec::new();
+            loop {
+                let n = stream.read(&mut buf).expect("read request");
+                assert!(n > 0, "request must arrive before peer closes");
+                request.extend_from_slice(&buf[..n]);
+                if request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    break;
+                }
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .expect("write response");
+            String::from_utf8(request).expect("utf8 request")
+        });
+
+        let client = HttpClient::builder()
+            .proxy("http://127.0.0.1:9")
+            .unix_socket(&path)
+            .build();
+        let cx = Cx::for_testing();
+        let response =
+            block_on(client.send_get(&cx, "http://sidecar/health")).expect("request over uds");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"ok");
+
+        let request = server.join().expect("server thread should join");
+        assert!(request.starts_with("GET /health HTTP/1.1\r\n"), "{request}");
+        assert!(
+            request.to_ascii_lowercase().contains("host: sidecar\r\n"),
+            "{request}"
+        );
+
+        let https = block_on(client.send_get(&cx, "https://sidecar/health"));
+        assert!(matches!(https, Err(ClientError::TlsError(_))));
+    }
+
     #[test]
     fn request_returns_cancelled_when_cx_is_cancelled_after_exchange() {
         use std::io::{Read, Write};
@@ -5283,6 +5730,119 @@ mod tests {
         );
     }
 
+    #[test]
+    fn endpoint_set_retries_failed_connect_on_another_endpoint() {
+        use crate::net::{BalancePolicy, EndpointSet, EndpointSetConfig};
+        use std::io::{Read, Write};
+
+        const RESPONSE: &[u8] =
+            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+        let dead = {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind dead listener");
+            listener.local_addr().expect("dead listener address")
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind live listener");
+        let live = listener.local_addr().expect("live listener address");
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().expect("accept");
+                let mut buf = [0_u8; 1024];
+                let mut request = Vec::new();
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).expect("read request");
+                    assert!(n > 0, "request must arrive before peer closes");
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8(request).expect("request should be utf8"));
+                stream.write_all(RESPONSE).expect("write response");
+            }
+            requests
+        });
+
+        let endpoints = EndpointSet::new(
+            EndpointSetConfig::new(BalancePolicy::RoundRobin),
+            [dead, live],
+        );
+        let client = HttpClient::builder()
+            .endpoint_set("backend.test:80", endpoints.clone())
+            .build();
+        let cx = Cx::for_testing();
+
+        for _ in 0..2 {
+            let url = "http://backend.test/health";
+            let response = block_on(client.request(&cx, Method::Get, url, Vec::new(), Vec::new()))
+                .expect("request should fail over to the live endpoint");
+            assert_eq!(response.status, 200);
+        }
+
+        let requests = server.join().expect("server thread should join");
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.contains("Host: backend.test\r\n")),
+            "balanced requests must keep the URL host"
+        );
+        let metrics = endpoints.metrics();
+        let dead_metrics = metrics
+            .iter()
+            .find(|metrics| metrics.addr == dead)
+            .expect("dead endpoint metrics");
+        let live_metrics = metrics
+            .iter()
+            .find(|metrics| metrics.addr == live)
+            .expect("live endpoint metrics");
+        assert!(dead_metrics.failures >= 1, "dead endpoint must record failures");
+        assert_eq!(live_metrics.requests, 2);
+        assert_eq!(live_metrics.failures, 0);
+        assert_eq!(live_metrics.in_flight, 0);
+    }
+
+    #[test]
+    fn error_phase_selects_retry_class() {
+        let refused = || io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+        let reset = || io::Error::new(io::ErrorKind::ConnectionReset, "reset");
+        let cases = [
+            (
+                ClientError::DnsError(io::Error::new(io::ErrorKind::NotFound, "nxdomain")),
+                Some(RequestPhase::Resolve),
+                RetryClass::ReResolve,
+            ),
+            (
+                ClientError::ConnectError(refused()),
+                Some(RequestPhase::Connect),
+                RetryClass::NextEndpoint,
+            ),
+            (
+                ClientError::TlsError("certificate verification failed".into()),
+                Some(RequestPhase::Handshake),
+                RetryClass::NextEndpoint,
+            ),
+            (
+                ClientError::Io(reset()),
+                Some(RequestPhase::Request),
+                RetryClass::NextEndpoint,
+            ),
+            (ClientError::DeadlineExceeded, None, RetryClass::Fail),
+        ];
+        for (err, phase, class) in cases {
+            assert_eq!(err.phase(), phase, "{err}");
+            assert_eq!(err.retry_class(&Method::Get), class, "{err}");
+        }
+
+        // A dropped transport after the request was written is only replayed
+        // for safe methods; earlier phases move on regardless of method.
+        assert_eq!(
+            ClientError::Io(reset()).retry_class(&Method::Post),
+            RetryClass::Fail
+        );
+        assert_eq!(
+            ClientError::TlsError("handshake timed out".into()).retry_class(&Method::Post),
+            RetryClass::NextEndpoint
+        );
+    }
+
     #[test]
     fn proxy_non_streaming_path_respects_max_body_size() {
         use crate::http::h1::codec::HttpError;
@@ -788,7 +788,7 @@ fn is_transient_accept_error(err: &io::Error) -> bool {
             | io::ErrorKind::ConnectionAborted
             | io::ErrorKind::ConnectionReset
             | io::ErrorKind::Interrupted
-    )
+    ) || crate::error::is_fd_exhaustion(err)
 }
 
 fn transient_accept_backoff_delay(streak: u32) -> Duration {
@@ -803,7 +803,10 @@ fn transient_accept_now() -> Time {
 }
 
 fn should_retry_after_spawn_failure(err: &SpawnError) -> bool {
-    matches!(err, SpawnError::RegionAtCapacity { .. })
+    matches!(
+        err,
+        SpawnError::RegionAtCapacity { .. } | SpawnError::AtCapacity { .. }
+    )
 }
 
 #[cfg(test)]
@@ -898,6 +901,10 @@ mod tests {
             io::ErrorKind::Interrupted,
             "interrupted"
         )));
+        assert!(is_transient_accept_error(&io::Error::new(
+            io::ErrorKind::QuotaExceeded,
+            crate::Error::fd_exhausted("accept")
+        )));
         assert!(!is_transient_accept_error(&io::Error::new(
             io::ErrorKind::AddrInUse,
             "in use"
@@ -34,7 +34,7 @@ pub use codec::{Http1Codec, HttpError};
 pub use codec::parse_header_line_test as parse_header_line;
 pub use http_client::{
     ClientError, ClientRequestBuilder, HttpClient, HttpClientBuilder, HttpClientConfig, ParsedUrl,
-    RedirectPolicy, RetryPolicy, Scheme,
+    RedirectPolicy, RequestPhase, RetryClass, RetryPolicy, Scheme,
 };
 #[cfg(not(target_arch = "wasm32"))]
 pub use listener::{Http1Listener, Http1ListenerConfig};
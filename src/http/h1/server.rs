@@ -12,15 +12,15 @@ use crate::http::h1::types::{Method, Request, Response, Version, default_reason}
 use crate::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use crate::server::shutdown::{ShutdownPhase, ShutdownSignal};
 use crate::stream::Stream;
-use crate::time::{timeout, wall_now};
-use crate::types::Budget;
+use crate::time::{TimerDriverHandle, TimerHandle, timeout, wall_now};
+use crate::types::{Budget, Time};
 use crate::web::request_region::{ServerHopOutcome, ServerRequestRegion, derive_request_budget};
 use std::future::{Future, poll_fn};
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 
 /// Host header validation policy for security against Host header injection attacks.
@@ -499,6 +499,7 @@ where
     async fn read_next<T>(
         &self,
         framed: &mut Framed<T, Http1Codec>,
+        timers: &mut ConnectionTimers,
         _state: &ConnectionState,
     ) -> Option<ReadOutcome>
     where
@@ -559,14 +560,35 @@ where
             .await
         };
 
-        if let Some(idle_timeout) = self.config.idle_timeout {
+        let Some(idle_timeout) = self.config.idle_timeout else {
+            return Some(read_future.await);
+        };
+        let Some(timer) = timers.idle_driver(idle_timeout) else {
             let now = Cx::current()
                 .and_then(|cx| cx.timer_driver())
                 .map_or_else(wall_now, |timer| timer.now());
-            timeout(now, idle_timeout, read_future).await.ok()
-        } else {
-            Some(read_future.await)
-        }
+            return timeout(now, idle_timeout, read_future).await.ok();
+        };
+
+        // The connection's idle timer is re-armed in place for each request
+        // instead of registering (and later cancelling) a fresh sleep. Like
+        // `timeout`, it never outlives the ambient deadline.
+        let idle_deadline = timer.now() + idle_timeout;
+        let deadline = Cx::current()
+            .and_then(|cx| cx.deadline())
+            .map_or(idle_deadline, |ambient| ambient.min(idle_deadline));
+        let mut read_future = std::pin::pin!(read_future);
+        poll_fn(|cx| {
+            if let Poll::Ready(outcome) = read_future.as_mut().poll(cx) {
+                return Poll::Ready(Some(outcome));
+            }
+            if timer.now() >= deadline {
+                return Poll::Ready(None);
+            }
+            timers.arm_idle(deadline, cx.waker());
+            Poll::Pending
+        })
+        .await
     }
 
     fn should_stop_reading(
@@ -616,6 +638,7 @@ where
                 .and_then(|cx| cx.timer_driver())
                 .map_or_else(wall_now, |timer| timer.now()),
         );
+        let mut timers = ConnectionTimers::new();
 
         loop {
             state.phase = ConnectionPhase::Idle;
@@ -652,7 +675,7 @@ where
 
             state.phase = ConnectionPhase::Reading;
 
-            let Some(read_outcome) = self.read_next(&mut framed, &state).await else {
+            let Some(read_outcome) = self.read_next(&mut framed, &mut timers, &state).await else {
                 state.phase = ConnectionPhase::Closing;
                 break;
             };
@@ -933,6 +956,78 @@ where
     }
 }
 
+/// Timers owned by one connection.
+///
+/// The idle timer is armed through [`TimerBatch`](crate::time::TimerBatch):
+/// each request moves its deadline with a reset, which rewrites it in place
+/// when it stays in the same wheel slot, and teardown cancels whatever is
+/// still armed in one batch. Without a timer driver on the connection's
+/// `Cx`, reads fall back to [`timeout`].
+struct ConnectionTimers {
+    timer: Option<TimerDriverHandle>,
+    idle: Option<ArmedTimer>,
+}
+
+struct ArmedTimer {
+    handle: TimerHandle,
+    deadline: Time,
+    waker: Waker,
+}
+
+impl ConnectionTimers {
+    fn new() -> Self {
+        Self {
+            timer: Cx::current().and_then(|cx| cx.timer_driver()),
+            idle: None,
+        }
+    }
+
+    /// Returns the driver to arm the idle timer on, unless there is none or
+    /// `idle_timeout` exceeds what it can represent without firing early.
+    fn idle_driver(&self, idle_timeout: Duration) -> Option<TimerDriverHandle> {
+        self.timer
+            .as_ref()
+            .filter(|timer| idle_timeout <= timer.max_timer_duration())
+            .cloned()
+    }
+
+    /// Ensures the idle timer is armed for `deadline` and wakes `waker`.
+    fn arm_idle(&mut self, deadline: Time, waker: &Waker) {
+        let Some(timer) = &self.timer else {
+            return;
+        };
+        let batch = timer.batch();
+        if let Some(armed) = &mut self.idle
+            && armed.waker.will_wake(waker)
+        {
+            if armed.deadline == deadline {
+                return;
+            }
+            // A reset finds the timer inactive only once it has fired.
+            if batch.reset_many(&[armed.handle], deadline).inactive == 0 {
+                armed.deadline = deadline;
+                return;
+            }
+        }
+        if let Some(stale) = self.idle.take() {
+            let _ = batch.cancel_many(&[stale.handle]);
+        }
+        self.idle = Some(ArmedTimer {
+            handle: timer.register(deadline, waker.clone()),
+            deadline,
+            waker: waker.clone(),
+        });
+    }
+}
+
+impl Drop for ConnectionTimers {
+    fn drop(&mut self) {
+        if let (Some(timer), Some(idle)) = (&self.timer, self.idle.take()) {
+            let _ = timer.batch().cancel_many(&[idle.handle]);
+        }
+    }
+}
+
 /// RAII guard for the listener-shared in-flight request counter
 /// (br-asupersync-server-stack-hardening-eeexl1.2, D2.2b).
 ///
@@ -2452,4 +2547,32 @@ mod tests {
         assert_eq!(protocol, "h1");
         assert_eq!(outcome.as_deref(), Some("ok"));
     }
+
+    #[test]
+    fn connection_idle_timer_resets_in_place_and_cancels_on_teardown() {
+        let clock = Arc::new(crate::time::VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(clock.clone());
+        let waker = Waker::noop().clone();
+        let mut timers = ConnectionTimers {
+            timer: Some(timer.clone()),
+            idle: None,
+        };
+
+        timers.arm_idle(Time::from_secs(60), &waker);
+        let first = timers.idle.as_ref().expect("idle timer armed").handle;
+        // Activity within the same wheel slot moves the timer, not a new one.
+        timers.arm_idle(Time::from_millis(60_100), &waker);
+        assert_eq!(timers.idle.as_ref().expect("idle timer armed").handle, first);
+        assert_eq!(timer.pending_count(), 1);
+
+        // Once the timer has fired, re-arming registers a fresh one.
+        clock.advance_to(Time::from_millis(60_100));
+        assert_eq!(timer.process_timers(), 1);
+        timers.arm_idle(Time::from_secs(120), &waker);
+        assert_ne!(timers.idle.as_ref().expect("idle timer armed").handle, first);
+        assert_eq!(timer.pending_count(), 1);
+
+        drop(timers);
+        assert!(timer.is_empty(), "teardown cancels the armed timer");
+    }
 }
@@ -357,12 +357,15 @@ const DEFAULT_MULTIPART_BOUNDARY: &str = "asupersync-boundary";
 pub enum MultipartError {
     /// Boundary is empty or contains invalid bytes for multipart/form-data.
     InvalidBoundary,
+    /// The boundary delimiter occurs inside an in-memory part.
+    BoundaryCollision,
 }
 
 impl fmt::Display for MultipartError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidBoundary => f.write_str("invalid multipart boundary"),
+            Self::BoundaryCollision => f.write_str("multipart boundary occurs in part content"),
         }
     }
 }
@@ -507,7 +510,7 @@ impl MultipartForm {
     }
 }
 
-fn is_valid_multipart_boundary(boundary: &str) -> bool {
+pub(crate) fn is_valid_multipart_boundary(boundary: &str) -> bool {
     if boundary.is_empty() || boundary.len() > 70 {
         return false;
     }
@@ -533,7 +536,7 @@ fn is_valid_multipart_boundary(boundary: &str) -> bool {
     })
 }
 
-fn escape_content_disposition_value(value: &str) -> String {
+pub(crate) fn escape_content_disposition_value(value: &str) -> String {
     let mut escaped = String::with_capacity(value.len());
     for ch in value.chars() {
         match ch {
@@ -577,7 +580,7 @@ fn is_valid_token(s: &str) -> bool {
 
 /// Sanitize a MIME content-type value by stripping CR, LF, and NUL characters
 /// that could be used for header injection in multipart bodies.
-fn sanitize_content_type(value: &str) -> String {
+pub(crate) fn sanitize_content_type(value: &str) -> String {
     value
         .chars()
         .filter(|&ch| ch != '\r' && ch != '\n' && ch != '\0')
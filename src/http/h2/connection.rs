@@ -35,6 +35,15 @@ const DEFAULT_RST_STREAM_RATE_LIMIT: u32 = 100;
 /// Default window duration for RST_STREAM rate limiting (in milliseconds).
 const DEFAULT_RST_STREAM_RATE_WINDOW_MS: u128 = 30_000;
 
+/// Default PING rate limit: max non-ACK PING frames within the window.
+///
+/// Protects against CVE-2019-9512 (Ping Flood) class attacks where a peer
+/// forces the server to queue an unbounded stream of PING ACKs.
+const DEFAULT_PING_RATE_LIMIT: u32 = 100;
+
+/// Default window duration for PING rate limiting (in milliseconds).
+const DEFAULT_PING_RATE_WINDOW_MS: u128 = 10_000;
+
 /// Last-stream-id advertised by the stage-1 graceful-shutdown GOAWAY.
 ///
 /// RFC 9113 §6.8: a graceful shutdown starts with a GOAWAY carrying the
@@ -72,6 +81,35 @@ impl Default for RstStreamRateLimit {
     }
 }
 
+/// Configurable PING rate limit for CVE-2019-9512 protection.
+///
+/// Only PING frames that demand an ACK are counted; ACKs for our own PINGs
+/// are free. Exceeding the limit terminates the connection with
+/// ENHANCE_YOUR_CALM.
+///
+/// # Defaults
+///
+/// | Parameter | Default | Meaning |
+/// |-----------|---------|---------|
+/// | `max_pings` | 100 | Max non-ACK PING frames per window |
+/// | `ping_window_ms` | 10,000 | Window duration in milliseconds |
+#[derive(Debug, Clone, Copy)]
+pub struct PingRateLimit {
+    /// Maximum non-ACK PING frames allowed within the window.
+    pub max_pings: u32,
+    /// Window duration in milliseconds.
+    pub ping_window_ms: u128,
+}
+
+impl Default for PingRateLimit {
+    fn default() -> Self {
+        Self {
+            max_pings: DEFAULT_PING_RATE_LIMIT,
+            ping_window_ms: DEFAULT_PING_RATE_WINDOW_MS,
+        }
+    }
+}
+
 fn wall_clock_now() -> Time {
     crate::time::wall_now()
 }
@@ -340,6 +378,12 @@ pub struct Connection {
     rst_stream_count: u32,
     /// Start of the current RST_STREAM rate-limit window.
     rst_stream_window_start: Time,
+    /// PING rate limit configuration.
+    ping_rate_limit: PingRateLimit,
+    /// Non-ACK PING frames received in the current rate-limit window.
+    ping_count: u32,
+    /// Start of the current PING rate-limit window.
+    ping_window_start: Time,
 }
 
 impl Connection {
@@ -394,6 +438,9 @@ impl Connection {
             rst_rate_limit: RstStreamRateLimit::default(),
             rst_stream_count: 0,
             rst_stream_window_start: time_getter(),
+            ping_rate_limit: PingRateLimit::default(),
+            ping_count: 0,
+            ping_window_start: time_getter(),
         }
     }
 
@@ -448,6 +495,9 @@ impl Connection {
             rst_rate_limit: RstStreamRateLimit::default(),
             rst_stream_count: 0,
             rst_stream_window_start: time_getter(),
+            ping_rate_limit: PingRateLimit::default(),
+            ping_count: 0,
+            ping_window_start: time_getter(),
         }
     }
 
@@ -461,6 +511,16 @@ impl Connection {
         self
     }
 
+    /// Configure the PING rate limit for CVE-2019-9512 protection.
+    ///
+    /// **Security warning**: Relaxing this limit lets a peer force unbounded
+    /// PING ACK work. Only increase if external DoS protection is in place.
+    #[must_use]
+    pub fn ping_rate_limit(mut self, limit: PingRateLimit) -> Self {
+        self.ping_rate_limit = limit;
+        self
+    }
+
     /// Get the connection state.
     #[must_use]
     pub fn state(&self) -> ConnectionState {
@@ -977,7 +1037,7 @@ impl Connection {
             Frame::RstStream(f) => self.process_rst_stream(f).map(Some),
             Frame::Settings(f) => self.process_settings(&f),
             Frame::PushPromise(f) => self.process_push_promise(&f),
-            Frame::Ping(f) => Ok(self.process_ping(f)),
+            Frame::Ping(f) => self.process_ping(f),
             Frame::GoAway(f) => Ok(Some(self.process_goaway(f))),
             Frame::WindowUpdate(f) => self.process_window_update(f),
             Frame::Continuation(f) => self.process_continuation(f),
@@ -1627,13 +1687,34 @@ impl Connection {
     }
 
     /// Process PING frame.
-    fn process_ping(&mut self, frame: PingFrame) -> Option<ReceivedFrame> {
+    ///
+    /// Non-ACK PINGs are rate limited (CVE-2019-9512 mitigation): more than
+    /// `max_pings` within `ping_window_ms` terminates the connection with
+    /// ENHANCE_YOUR_CALM.
+    fn process_ping(&mut self, frame: PingFrame) -> Result<Option<ReceivedFrame>, H2Error> {
         if !frame.ack {
+            let now = (self.time_getter)();
+            let elapsed =
+                std::time::Duration::from_nanos(now.duration_since(self.ping_window_start))
+                    .as_millis();
+            if elapsed >= self.ping_rate_limit.ping_window_ms {
+                self.ping_count = 0;
+                self.ping_window_start = now;
+            }
+            // Same fail-closed shape as the RST_STREAM limiter: N allowed,
+            // N+1 rejected, no wrapping at `u32::MAX`.
+            if self.ping_count >= self.ping_rate_limit.max_pings {
+                return Err(H2Error::connection(
+                    ErrorCode::EnhanceYourCalm,
+                    "PING flood detected",
+                ));
+            }
+            self.ping_count += 1;
             // Send PING ACK
             self.pending_ops
                 .push_back(PendingOp::PingAck(frame.opaque_data));
         }
-        None
+        Ok(None)
     }
 
     /// Process GOAWAY frame.
@@ -5677,6 +5758,69 @@ mod tests {
         assert_eq!(conn.rst_stream_count, u32::MAX);
     }
 
+    /// CVE-2019-9512: PING flood beyond rate limit triggers ENHANCE_YOUR_CALM.
+    #[test]
+    fn ping_flood_triggers_enhance_your_calm() {
+        let mut conn = Connection::server(Settings::default());
+        conn.state = ConnectionState::Open;
+
+        for i in 0..DEFAULT_PING_RATE_LIMIT {
+            let opaque = u64::from(i).to_be_bytes();
+            conn.process_frame(Frame::Ping(PingFrame::new(opaque)))
+                .expect("PING within the limit is answered");
+        }
+
+        let err = conn
+            .process_frame(Frame::Ping(PingFrame::new([0xFF; 8])))
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::EnhanceYourCalm);
+        assert!(
+            err.stream_id.is_none(),
+            "PING flood must be a connection error"
+        );
+    }
+
+    #[test]
+    fn ping_acks_do_not_count_toward_ping_rate_limit() {
+        let mut conn = Connection::server(Settings::default()).ping_rate_limit(PingRateLimit {
+            max_pings: 1,
+            ping_window_ms: DEFAULT_PING_RATE_WINDOW_MS,
+        });
+        conn.state = ConnectionState::Open;
+
+        for _ in 0..8 {
+            conn.process_frame(Frame::Ping(PingFrame::ack([0; 8])))
+                .expect("PING ACKs are never rate limited");
+        }
+        conn.process_frame(Frame::Ping(PingFrame::new([1; 8])))
+            .expect("first PING is within the limit");
+        let err = conn
+            .process_frame(Frame::Ping(PingFrame::new([2; 8])))
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::EnhanceYourCalm);
+    }
+
+    #[test]
+    fn ping_rate_limit_window_uses_time_getter() {
+        let _clock = lock_test_clock();
+        set_test_time_offset(Duration::ZERO);
+        let mut conn = Connection::server_with_time_getter(Settings::default(), test_now);
+        conn.state = ConnectionState::Open;
+
+        for _ in 0..DEFAULT_PING_RATE_LIMIT {
+            conn.process_frame(Frame::Ping(PingFrame::new([0; 8])))
+                .unwrap();
+        }
+
+        advance_test_time(Duration::from_millis(
+            u64::try_from(DEFAULT_PING_RATE_WINDOW_MS).expect("window fits u64") + 1,
+        ));
+
+        conn.process_frame(Frame::Ping(PingFrame::new([0; 8])))
+            .expect("rate-limit window should reset");
+        assert_eq!(conn.ping_count, 1);
+    }
+
     /// Regression: HEADERS on a stream with invalid parity must NOT bump
     /// last_stream_id. If it did, a subsequent GOAWAY would advertise a higher
     /// last_stream_id than actually processed, violating RFC 7540 §6.8.
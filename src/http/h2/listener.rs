@@ -13,19 +13,31 @@
 //! handler dispatch through a response funnel, and request-aware graceful
 //! drain via the D2.3 two-stage GOAWAY primitives on
 //! [`crate::http::h2::connection::Connection`].
+//!
+//! Cleartext listeners speak HTTP/2 with prior knowledge. With TLS
+//! ([`Http2Listener::with_tls`], `tls` feature) the protocol is chosen per
+//! connection by ALPN: `h2` runs the frame driver, anything else falls back
+//! to the HTTP/1.1 server with the same handler. Per-connection flood limits
+//! ([`RstStreamRateLimit`], [`PingRateLimit`]) and the advertised
+//! [`Settings`] (concurrent streams, windows, frame size, header list size,
+//! HPACK table size) come from [`Http2ListenerConfig`].
 
 use crate::channel::mpsc;
 use crate::codec::Framed;
 use crate::cx::Cx;
-use crate::http::h1::server::{HostPolicy, parse_request_timeout_header, validate_host_header};
+use crate::http::h1::server::{
+    HostPolicy, Http1Config, parse_request_timeout_header, validate_host_header,
+};
 use crate::http::h1::types::{Method, Request, Response, Version};
-use crate::http::h2::connection::{CLIENT_PREFACE, Connection, FrameCodec, ReceivedFrame};
+use crate::http::h2::connection::{
+    CLIENT_PREFACE, Connection, FrameCodec, PingRateLimit, ReceivedFrame, RstStreamRateLimit,
+};
 use crate::http::h2::error::{ErrorCode, H2Error};
 use crate::http::h2::frame::Frame;
 use crate::http::h2::hpack::Header;
 use crate::http::h2::settings::Settings;
 use crate::http::h2::stream::StreamState;
-use crate::io::AsyncReadExt as _;
+use crate::io::{AsyncRead, AsyncReadExt as _, AsyncWrite};
 use crate::net::tcp::listener::TcpListener;
 use crate::net::tcp::stream::TcpStream;
 use crate::runtime::{JoinHandle, RuntimeHandle, SpawnError};
@@ -255,7 +267,7 @@ fn is_transient_accept_error(err: &io::Error) -> bool {
             | io::ErrorKind::ConnectionAborted
             | io::ErrorKind::ConnectionReset
             | io::ErrorKind::Interrupted
-    )
+    ) || crate::error::is_fd_exhaustion(err)
 }
 
 /// Exponential backoff delay for a streak of transient accept errors so a
@@ -271,7 +283,10 @@ fn transient_accept_backoff_delay(streak: u32) -> Duration {
 /// task capacity) should drop that one connection and keep accepting, not
 /// tear down the whole listener (h1 parity).
 fn should_retry_after_spawn_failure(err: &SpawnError) -> bool {
-    matches!(err, SpawnError::RegionAtCapacity { .. })
+    matches!(
+        err,
+        SpawnError::RegionAtCapacity { .. } | SpawnError::AtCapacity { .. }
+    )
 }
 
 /// Connection-specific h1 headers that MUST NOT be carried into HTTP/2
@@ -1015,9 +1030,9 @@ enum DriverEvent {
 /// Flow-control-blocked DATA stays queued inside the connection (its
 /// `next_frame` re-queues it) and is retried after the next processed
 /// frame (e.g. a WINDOW_UPDATE) pumps again.
-async fn pump_writes(
+async fn pump_writes<T: AsyncWrite + Unpin>(
     conn: &mut Connection,
-    framed: &mut Framed<TcpStream, FrameCodec>,
+    framed: &mut Framed<T, FrameCodec>,
 ) -> io::Result<()> {
     while let Some(frame) = conn.next_frame() {
         framed.send(frame).map_err(io::Error::other)?;
@@ -1027,8 +1042,8 @@ async fn pump_writes(
 
 /// Wait for the next driver event: incoming frame, completed handler
 /// response, or a shutdown-phase transition.
-async fn next_driver_event(
-    framed: &mut Framed<TcpStream, FrameCodec>,
+async fn next_driver_event<T: AsyncRead + Unpin>(
+    framed: &mut Framed<T, FrameCodec>,
     resp_rx: &mut mpsc::Receiver<FunnelItem>,
     task_cx: &Cx,
     signal: &ShutdownSignal,
@@ -1342,28 +1357,31 @@ fn frame_codec_for(max_frame_size: u32) -> FrameCodec {
     codec
 }
 
+/// Build the sans-I/O server connection for one accepted transport: local
+/// SETTINGS queued, initial connection window raised, and the configured
+/// RST_STREAM / PING flood limits installed.
+fn server_connection_for(config: &Http2ListenerConfig) -> io::Result<Connection> {
+    let mut conn = Connection::server_with_time_getter(config.settings.clone(), config.time_getter)
+        .rst_stream_rate_limit(config.rst_stream_rate_limit)
+        .ping_rate_limit(config.ping_rate_limit);
+    conn.queue_initial_settings();
+    conn.set_initial_connection_recv_window(config.initial_connection_window_size)
+        .map_err(io::Error::other)?;
+    Ok(conn)
+}
+
 #[allow(clippy::too_many_lines)]
-#[allow(clippy::too_many_arguments)]
-async fn serve_h2_connection<F, Fut, R>(
-    mut stream: TcpStream,
+async fn serve_h2_connection<T, F, Fut, R>(
+    mut stream: T,
     peer_addr: Option<SocketAddr>,
     handler: Arc<F>,
-    settings: Settings,
-    initial_connection_window_size: u32,
+    config: Http2ListenerConfig,
     shutdown_signal: ShutdownSignal,
     in_flight_requests: Arc<AtomicUsize>,
     runtime: RuntimeHandle,
-    max_body_size: usize,
-    host_policy: HostPolicy,
-    request_timeout: Option<Duration>,
-    request_timeout_header_cap: Option<Duration>,
-    request_drain_grace: Duration,
-    max_requests_per_connection: Option<u64>,
-    idle_timeout: Option<Duration>,
-    stream_idle_timeout: Option<Duration>,
-    time_getter: fn() -> Time,
 ) -> io::Result<()>
 where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
     F: Fn(Request) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = R> + Send + 'static,
     R: IntoHttp2Response + Send + 'static,
@@ -1387,11 +1405,20 @@ where
     // conformant peer frame sized within the advertised limit would be wrongly
     // rejected with FRAME_SIZE_ERROR. The accept limit is always the LOCAL
     // advertised value, never the peer's (br-asupersync-i1r9cw).
-    let local_max_frame_size = settings.max_frame_size;
-    let mut conn = Connection::server_with_time_getter(settings, time_getter);
-    conn.queue_initial_settings();
-    conn.set_initial_connection_recv_window(initial_connection_window_size)
-        .map_err(io::Error::other)?;
+    let local_max_frame_size = config.settings.max_frame_size;
+    let mut conn = server_connection_for(&config)?;
+    let Http2ListenerConfig {
+        max_body_size,
+        allowed_hosts: host_policy,
+        request_timeout,
+        request_timeout_header_cap,
+        request_drain_grace,
+        max_requests_per_connection,
+        idle_timeout,
+        stream_idle_timeout,
+        time_getter,
+        ..
+    } = config;
     let mut framed = Framed::new(stream, frame_codec_for(local_max_frame_size));
 
     let (resp_tx, mut resp_rx) = mpsc::channel::<FunnelItem>(RESPONSE_FUNNEL_CAPACITY);
@@ -1730,6 +1757,113 @@ where
     }
 }
 
+/// Application protocol selected for a TLS connection by ALPN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegotiatedProtocol {
+    /// The client and server agreed on `h2`.
+    Http2,
+    /// ALPN was absent or selected anything other than `h2`.
+    Http1,
+}
+
+impl NegotiatedProtocol {
+    /// Map the ALPN protocol negotiated during the TLS handshake.
+    ///
+    /// Only an explicit `h2` selects HTTP/2 (RFC 9113 §3.2); a client that
+    /// omits ALPN or negotiates `http/1.1` is served over HTTP/1.1
+    /// (RFC 7301 §3.2 leaves the fallback to the server).
+    #[must_use]
+    pub fn from_alpn(alpn: Option<&[u8]>) -> Self {
+        match alpn {
+            Some(b"h2") => Self::Http2,
+            _ => Self::Http1,
+        }
+    }
+}
+
+/// Complete the TLS handshake on an accepted connection and serve it with
+/// the protocol ALPN selected: the HTTP/2 driver for `h2`, otherwise the
+/// HTTP/1.1 server with the same handler and shutdown wiring.
+#[cfg(feature = "tls")]
+#[allow(clippy::too_many_arguments)]
+async fn serve_tls_connection<F, Fut, R>(
+    acceptor: &crate::tls::TlsAcceptor,
+    stream: TcpStream,
+    peer_addr: Option<SocketAddr>,
+    handler: Arc<F>,
+    config: Http2ListenerConfig,
+    shutdown_signal: ShutdownSignal,
+    in_flight_requests: Arc<AtomicUsize>,
+    runtime: RuntimeHandle,
+) -> io::Result<()>
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: IntoHttp2Response + Send + 'static,
+{
+    let tls_stream = acceptor.accept(stream).await.map_err(io::Error::other)?;
+    match NegotiatedProtocol::from_alpn(tls_stream.alpn_protocol()) {
+        NegotiatedProtocol::Http2 => {
+            serve_h2_connection(
+                tls_stream,
+                peer_addr,
+                handler,
+                config,
+                shutdown_signal,
+                in_flight_requests,
+                runtime,
+            )
+            .await
+        }
+        NegotiatedProtocol::Http1 => {
+            serve_h1_fallback(
+                tls_stream,
+                peer_addr,
+                handler,
+                config.http1_fallback,
+                shutdown_signal,
+                in_flight_requests,
+            )
+            .await
+        }
+    }
+}
+
+/// Serve a connection over HTTP/1.1 with an HTTP/2 listener's handler.
+///
+/// Server pushes attached to an [`Http2Response`] have no HTTP/1.1
+/// equivalent and are dropped; the main response is sent unchanged.
+#[cfg(feature = "tls")]
+async fn serve_h1_fallback<T, F, Fut, R>(
+    io: T,
+    peer_addr: Option<SocketAddr>,
+    handler: Arc<F>,
+    config: Http1Config,
+    shutdown_signal: ShutdownSignal,
+    in_flight_requests: Arc<AtomicUsize>,
+) -> io::Result<()>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: IntoHttp2Response + Send + 'static,
+{
+    let server = crate::http::h1::server::Http1Server::with_config(
+        move |request: Request| {
+            let response = handler(request);
+            async move { response.await.into_h2_response().response }
+        },
+        config,
+    )
+    .with_shutdown_signal(shutdown_signal)
+    .with_in_flight_requests(in_flight_requests);
+    server
+        .serve_with_peer_addr(io, peer_addr)
+        .await
+        .map(|_| ())
+        .map_err(io::Error::other)
+}
+
 /// Configuration for the HTTP/2 listener (br-asupersync-eprpk6).
 #[derive(Debug, Clone)]
 pub struct Http2ListenerConfig {
@@ -1798,6 +1932,17 @@ pub struct Http2ListenerConfig {
     /// with CANCEL and drops the associated handler future; the multiplexed
     /// connection remains available to other streams. `None` disables it.
     pub stream_idle_timeout: Option<Duration>,
+    /// Per-connection RST_STREAM rate limit (CVE-2023-44487 Rapid Reset
+    /// mitigation). A peer exceeding it is disconnected with an
+    /// ENHANCE_YOUR_CALM GOAWAY.
+    pub rst_stream_rate_limit: RstStreamRateLimit,
+    /// Per-connection PING rate limit (CVE-2019-9512 Ping Flood mitigation).
+    /// A peer exceeding it is disconnected with an ENHANCE_YOUR_CALM GOAWAY.
+    pub ping_rate_limit: PingRateLimit,
+    /// HTTP/1.1 configuration used for TLS connections whose ALPN did not
+    /// select `h2` (see [`Http2Listener::with_tls`]). Unused for cleartext
+    /// prior-knowledge listeners.
+    pub http1_fallback: Http1Config,
     /// Time source for shutdown bookkeeping and drain supervision.
     pub time_getter: fn() -> Time,
 }
@@ -1825,6 +1970,9 @@ impl Default for Http2ListenerConfig {
             max_requests_per_connection: Some(1000),
             idle_timeout: Some(Duration::from_secs(60)),
             stream_idle_timeout: None,
+            rst_stream_rate_limit: RstStreamRateLimit::default(),
+            ping_rate_limit: PingRateLimit::default(),
+            http1_fallback: Http1Config::default(),
             time_getter: default_h2_listener_time_getter,
         }
     }
@@ -1943,6 +2091,30 @@ impl Http2ListenerConfig {
         self
     }
 
+    /// Set the per-connection RST_STREAM rate limit.
+    ///
+    /// **Security warning**: relaxing the default exposes the server to
+    /// Rapid Reset attacks (CVE-2023-44487).
+    #[must_use]
+    pub fn rst_stream_rate_limit(mut self, limit: RstStreamRateLimit) -> Self {
+        self.rst_stream_rate_limit = limit;
+        self
+    }
+
+    /// Set the per-connection PING rate limit.
+    #[must_use]
+    pub fn ping_rate_limit(mut self, limit: PingRateLimit) -> Self {
+        self.ping_rate_limit = limit;
+        self
+    }
+
+    /// Set the HTTP/1.1 configuration used when ALPN falls back from `h2`.
+    #[must_use]
+    pub fn http1_fallback(mut self, config: Http1Config) -> Self {
+        self.http1_fallback = config;
+        self
+    }
+
     /// Set the time source for listener bookkeeping.
     #[must_use]
     pub fn time_getter(mut self, time_getter: fn() -> Time) -> Self {
@@ -1972,6 +2144,8 @@ pub struct Http2Listener<F> {
     connection_manager: ConnectionManager,
     stats: Arc<Http2ListenerStats>,
     in_flight_requests: Arc<AtomicUsize>,
+    #[cfg(feature = "tls")]
+    tls_acceptor: Option<crate::tls::TlsAcceptor>,
 }
 
 impl<F, Fut, R> Http2Listener<F>
@@ -2021,9 +2195,30 @@ where
             connection_manager,
             stats,
             in_flight_requests: Arc::new(AtomicUsize::new(0)),
+            #[cfg(feature = "tls")]
+            tls_acceptor: None,
         }
     }
 
+    /// Terminate TLS on every accepted connection and pick the protocol by
+    /// ALPN.
+    ///
+    /// Connections that negotiate `h2` are served by the HTTP/2 driver; all
+    /// others (no ALPN, or `http/1.1`) fall back to HTTP/1.1 using
+    /// [`Http2ListenerConfig::http1_fallback`], with the same handler,
+    /// shutdown signal, and in-flight accounting. Build the acceptor with
+    /// [`TlsAcceptorBuilder::alpn_http`] so both protocols are offered.
+    /// Without TLS the listener speaks cleartext HTTP/2 with prior
+    /// knowledge.
+    ///
+    /// [`TlsAcceptorBuilder::alpn_http`]: crate::tls::TlsAcceptorBuilder::alpn_http
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn with_tls(mut self, acceptor: crate::tls::TlsAcceptor) -> Self {
+        self.tls_acceptor = Some(acceptor);
+        self
+    }
+
     /// Returns a clone of the shutdown signal for external phase observation.
     #[must_use]
     pub fn shutdown_signal(&self) -> ShutdownSignal {
@@ -2134,43 +2329,54 @@ where
             };
 
             let handler = Arc::clone(&self.handler);
-            let settings = self.config.settings.clone();
-            let initial_connection_window_size = self.config.initial_connection_window_size;
+            let config = self.config.clone();
             let shutdown_signal = self.shutdown_signal.clone();
             let in_flight_requests = Arc::clone(&self.in_flight_requests);
             let runtime_for_conn = runtime.clone();
-            let max_body_size = self.config.max_body_size;
-            let host_policy = self.config.allowed_hosts.clone();
-            let request_timeout = self.config.request_timeout;
-            let request_timeout_header_cap = self.config.request_timeout_header_cap;
-            let request_drain_grace = self.config.request_drain_grace;
-            let max_requests_per_connection = self.config.max_requests_per_connection;
-            let idle_timeout = self.config.idle_timeout;
-            let stream_idle_timeout = self.config.stream_idle_timeout;
-            let conn_time_getter = self.config.time_getter;
+            #[cfg(feature = "tls")]
+            let tls_acceptor = self.tls_acceptor.clone();
             let spawn_result = runtime.try_spawn(async move {
                 let peer_addr = Some(addr);
-                if let Err(err) = serve_h2_connection(
+                #[cfg(feature = "tls")]
+                let result = match tls_acceptor {
+                    Some(acceptor) => {
+                        serve_tls_connection(
+                            &acceptor,
+                            stream,
+                            peer_addr,
+                            handler,
+                            config,
+                            shutdown_signal,
+                            in_flight_requests,
+                            runtime_for_conn,
+                        )
+                        .await
+                    }
+                    None => {
+                        serve_h2_connection(
+                            stream,
+                            peer_addr,
+                            handler,
+                            config,
+                            shutdown_signal,
+                            in_flight_requests,
+                            runtime_for_conn,
+                        )
+                        .await
+                    }
+                };
+                #[cfg(not(feature = "tls"))]
+                let result = serve_h2_connection(
                     stream,
                     peer_addr,
                     handler,
-                    settings,
-                    initial_connection_window_size,
+                    config,
                     shutdown_signal,
                     in_flight_requests,
                     runtime_for_conn,
-                    max_body_size,
-                    host_policy,
-                    request_timeout,
-                    request_timeout_header_cap,
-                    request_drain_grace,
-                    max_requests_per_connection,
-                    idle_timeout,
-                    stream_idle_timeout,
-                    conn_time_getter,
                 )
-                .await
-                {
+                .await;
+                if let Err(err) = result {
                     // Bind unconditionally: tracing_compat::error! compiles
                     // to nothing without the tracing feature.
                     let _ = &err;
@@ -3734,4 +3940,116 @@ mod tests {
         assert!(response.body.is_empty());
         assert_eq!(response.header_value("content-length"), Some("999"));
     }
+
+    #[test]
+    fn alpn_selects_h2_only_when_negotiated() {
+        assert_eq!(
+            NegotiatedProtocol::from_alpn(Some(b"h2")),
+            NegotiatedProtocol::Http2
+        );
+        assert_eq!(
+            NegotiatedProtocol::from_alpn(Some(b"http/1.1")),
+            NegotiatedProtocol::Http1
+        );
+        assert_eq!(
+            NegotiatedProtocol::from_alpn(None),
+            NegotiatedProtocol::Http1,
+            "a client that does not offer h2 falls back to HTTP/1.1"
+        );
+        assert_eq!(
+            NegotiatedProtocol::from_alpn(Some(b"h2c")),
+            NegotiatedProtocol::Http1,
+            "h2c is never valid over TLS"
+        );
+    }
+
+    #[test]
+    fn server_connection_advertises_configured_settings() {
+        let config = Http2ListenerConfig::default()
+            .settings(
+                crate::http::h2::settings::SettingsBuilder::server()
+                    .max_concurrent_streams(8)
+                    .header_table_size(1024)
+                    .build(),
+            )
+            .initial_connection_window_size(1 << 20);
+        let mut conn = server_connection_for(&config).expect("valid config");
+
+        let Some(Frame::Settings(settings)) = conn.next_frame() else {
+            panic!("server must open with SETTINGS");
+        };
+        assert!(
+            settings
+                .settings
+                .contains(&crate::http::h2::frame::Setting::MaxConcurrentStreams(8))
+        );
+        assert!(
+            settings
+                .settings
+                .contains(&crate::http::h2::frame::Setting::HeaderTableSize(1024))
+        );
+        let Some(Frame::WindowUpdate(update)) = conn.next_frame() else {
+            panic!("connection window raise must follow SETTINGS");
+        };
+        assert_eq!(update.stream_id, 0);
+        assert_eq!(update.increment, (1 << 20) - 65_535);
+    }
+
+    #[test]
+    fn server_connection_enforces_configured_reset_flood_limit() {
+        let config = Http2ListenerConfig::default().rst_stream_rate_limit(RstStreamRateLimit {
+            max_rst_streams: 2,
+            rst_window_ms: 60_000,
+        });
+        let mut conn = server_connection_for(&config).expect("valid config");
+        conn.process_frame(Frame::Settings(crate::http::h2::frame::SettingsFrame::new(
+            Vec::new(),
+        )))
+        .expect("client settings accepted");
+
+        let mut last = Ok(None);
+        for stream_id in [1, 3, 5] {
+            let block = encode_hpack_test_headers(&[
+                (":method", "GET"),
+                (":scheme", "https"),
+                (":path", "/"),
+                (":authority", "example.com"),
+            ]);
+            conn.process_frame(Frame::Headers(crate::http::h2::frame::HeadersFrame::new(
+                stream_id, block, false, true,
+            )))
+            .expect("request headers accepted");
+            last = conn.process_frame(Frame::RstStream(
+                crate::http::h2::frame::RstStreamFrame::new(stream_id, ErrorCode::Cancel),
+            ));
+            if stream_id < 5 {
+                assert!(last.is_ok(), "resets within the limit are tolerated");
+            }
+        }
+        let err = last.expect_err("third reset exceeds the configured limit");
+        assert_eq!(err.code, ErrorCode::EnhanceYourCalm);
+        assert!(err.stream_id.is_none());
+    }
+
+    #[test]
+    fn server_connection_enforces_configured_ping_flood_limit() {
+        let config = Http2ListenerConfig::default().ping_rate_limit(PingRateLimit {
+            max_pings: 3,
+            ping_window_ms: 60_000,
+        });
+        let mut conn = server_connection_for(&config).expect("valid config");
+        conn.process_frame(Frame::Settings(crate::http::h2::frame::SettingsFrame::new(
+            Vec::new(),
+        )))
+        .expect("client settings accepted");
+
+        for _ in 0..3 {
+            conn.process_frame(Frame::Ping(crate::http::h2::frame::PingFrame::new([7; 8])))
+                .expect("PING within the limit is answered");
+        }
+        let err = conn
+            .process_frame(Frame::Ping(crate::http::h2::frame::PingFrame::new([8; 8])))
+            .expect_err("fourth PING exceeds the configured limit");
+        assert_eq!(err.code, ErrorCode::EnhanceYourCalm);
+    }
 }
@@ -23,7 +23,7 @@ pub mod settings;
 pub mod stream;
 
 // Re-export commonly used types
-pub use connection::{Connection, ConnectionState, FrameCodec};
+pub use connection::{Connection, ConnectionState, FrameCodec, PingRateLimit, RstStreamRateLimit};
 pub use error::{ErrorCode, H2Error};
 pub use frame::{Frame, FrameHeader, FrameType, Setting};
 pub use hpack::{Decoder as HpackDecoder, Encoder as HpackEncoder, Header};
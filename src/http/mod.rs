@@ -45,6 +45,7 @@ pub mod h3 {
     };
 }
 pub mod h3_native;
+pub mod multipart;
 pub mod pool;
 
 /// High-level pooled [`Client`] facade.
@@ -56,8 +57,8 @@ pub mod client;
 pub use body::{Body, Empty, Frame, Full, HeaderMap, HeaderName, HeaderValue, SizeHint};
 pub use h1::{
     ClientError, ClientRequestBuilder, HttpClient, HttpClientBuilder, HttpClientConfig, Method,
-    MultipartError, MultipartForm, ParsedUrl, RedirectPolicy, Request, RequestBuilder, Response,
-    ResponseBuilder, RetryPolicy, Scheme, StatusCode, Version,
+    MultipartError, MultipartForm, ParsedUrl, RedirectPolicy, Request, RequestBuilder,
+    RequestPhase, Response, ResponseBuilder, RetryClass, RetryPolicy, Scheme, StatusCode, Version,
 };
 
 /// Ergonomic, capability-gated handle for the high-level pooled HTTP client.
@@ -65,6 +66,7 @@ pub use h1::{
 /// See [`client`] for the no-ambient-global philosophy and
 /// [`Client::default_for_runtime`](client::Client::default_for_runtime).
 pub use client::Client;
+pub use multipart::{MultipartBody, MultipartBodyBuilder};
 
 // br-asupersync-um5wbj: H3Error is the public-facing alias for
 // H3NativeError; expose it unconditionally (was previously gated behind
@@ -0,0 +1,547 @@
+//! Streaming `multipart/form-data` request bodies.
+//!
+//! [`MultipartBody`] encodes a form whose parts are either held in memory or
+//! streamed from any [`AsyncRead`] (for example a [`crate::fs::File`]). It
+//! implements [`Body`], so it can be sent with
+//! [`HttpClient::request_multipart_body`](crate::http::HttpClient::request_multipart_body)
+//! or [`Http1Client::request_with_body`](crate::http::h1::Http1Client::request_with_body).
+//!
+//! # Framing
+//!
+//! When every part has a known length the body reports an exact
+//! [`SizeHint`] and is sent with `Content-Length`; a single unsized reader
+//! switches the request to `Transfer-Encoding: chunked`.
+//!
+//! # Boundaries
+//!
+//! Unless one is supplied, the boundary is 128 random bits drawn from the
+//! current [`Cx`](crate::cx::Cx) entropy source (deterministic under the lab
+//! runtime) or from OS entropy outside a task. In-memory parts are checked
+//! for the delimiter before the body is built; streamed parts cannot be
+//! checked up front and rely on the boundary being unguessable.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::http::MultipartBody;
+//!
+//! let file = asupersync::fs::File::open("report.csv").await?;
+//! let body = MultipartBody::builder()
+//!     .text("title", "Q3 report")
+//!     .file("upload", "report.csv", "text/csv", file)
+//!     .await?
+//!     .build()?;
+//! let response = client
+//!     .request_multipart_body(&cx, Method::Post, "http://example.com/upload", vec![], body)
+//!     .await?;
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::bytes::{Bytes, BytesCursor};
+use crate::http::body::{Body, Frame, SizeHint};
+use crate::http::h1::types::{
+    MultipartError, escape_content_disposition_value, is_valid_multipart_boundary,
+    sanitize_content_type,
+};
+use crate::io::{AsyncRead, ReadBuf};
+
+/// Largest data frame produced while streaming a reader part (64 KiB).
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Attempts at drawing a boundary that does not occur in any in-memory part.
+const MAX_BOUNDARY_ATTEMPTS: usize = 8;
+
+/// A boxed reader feeding one streamed part.
+type PartReader = Pin<Box<dyn AsyncRead + Send>>;
+
+enum PartData {
+    Bytes(Bytes),
+    Reader { reader: PartReader, len: Option<u64> },
+}
+
+struct PendingPart {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    data: PartData,
+}
+
+impl PendingPart {
+    fn head(&self, boundary: &str) -> Bytes {
+        let mut head = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"",
+            escape_content_disposition_value(&self.name)
+        );
+        if let Some(filename) = &self.filename {
+            head.push_str("; filename=\"");
+            head.push_str(&escape_content_disposition_value(filename));
+            head.push('"');
+        }
+        head.push_str("\r\n");
+        if let Some(content_type) = &self.content_type {
+            head.push_str("Content-Type: ");
+            head.push_str(&sanitize_content_type(content_type));
+            head.push_str("\r\n");
+        }
+        head.push_str("\r\n");
+        Bytes::from(head.into_bytes())
+    }
+}
+
+/// Builder for a [`MultipartBody`].
+///
+/// Parts are encoded in the order they are added.
+pub struct MultipartBodyBuilder {
+    boundary: Option<String>,
+    parts: Vec<PendingPart>,
+}
+
+impl fmt::Debug for MultipartBodyBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultipartBodyBuilder")
+            .field("boundary", &self.boundary)
+            .field("parts", &self.parts.len())
+            .finish()
+    }
+}
+
+impl MultipartBodyBuilder {
+    /// Use a caller-provided boundary instead of a random one.
+    ///
+    /// Returns [`MultipartError::InvalidBoundary`] when the boundary is empty,
+    /// longer than 70 bytes, or contains bytes outside RFC 2046 `bchars`.
+    pub fn boundary(mut self, boundary: impl Into<String>) -> Result<Self, MultipartError> {
+        let boundary = boundary.into();
+        if !is_valid_multipart_boundary(&boundary) {
+            return Err(MultipartError::InvalidBoundary);
+        }
+        self.boundary = Some(boundary);
+        Ok(self)
+    }
+
+    /// Add a text field.
+    #[must_use]
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(PendingPart {
+            name: name.into(),
+            filename: None,
+            content_type: None,
+            data: PartData::Bytes(Bytes::from(value.into().into_bytes())),
+        });
+        self
+    }
+
+    /// Add an in-memory file part.
+    #[must_use]
+    pub fn bytes(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        self.parts.push(PendingPart {
+            name: name.into(),
+            filename: Some(filename.into()),
+            content_type: Some(content_type.into()),
+            data: PartData::Bytes(data.into()),
+        });
+        self
+    }
+
+    /// Add a file part streamed from `reader`.
+    ///
+    /// With `len` set, the reader must yield exactly that many bytes; the
+    /// body fails with [`io::ErrorKind::UnexpectedEof`] if it ends early and
+    /// stops reading once `len` bytes have been sent. With `len` unset, the
+    /// part runs until the reader reports EOF and the body is sent chunked.
+    #[must_use]
+    pub fn reader<R>(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        reader: R,
+        len: Option<u64>,
+    ) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        self.parts.push(PendingPart {
+            name: name.into(),
+            filename: Some(filename.into()),
+            content_type: Some(content_type.into()),
+            data: PartData::Reader {
+                reader: Box::pin(reader),
+                len,
+            },
+        });
+        self
+    }
+
+    /// Add a file part streamed from an open file, sized by its metadata.
+    ///
+    /// The file is read from its current position, which should be the start.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn file(
+        self,
+        name: impl Into<String> + Send,
+        filename: impl Into<String> + Send,
+        content_type: impl Into<String> + Send,
+        file: crate::fs::File,
+    ) -> io::Result<Self> {
+        let len = file.metadata().await?.len();
+        Ok(self.reader(name, filename, content_type, file, Some(len)))
+    }
+
+    /// Encode the parts into a [`MultipartBody`].
+    ///
+    /// Returns [`MultipartError::BoundaryCollision`] when a caller-provided
+    /// boundary occurs inside an in-memory part. A generated boundary is
+    /// redrawn until it is collision-free.
+    pub fn build(self) -> Result<MultipartBody, MultipartError> {
+        let boundary = match self.boundary {
+            Some(boundary) if collides(&self.parts, &boundary) => {
+                return Err(MultipartError::BoundaryCollision);
+            }
+            Some(boundary) => boundary,
+            None => (0..MAX_BOUNDARY_ATTEMPTS)
+                .map(|_| generate_boundary())
+                .find(|boundary| !collides(&self.parts, boundary))
+                .ok_or(MultipartError::BoundaryCollision)?,
+        };
+
+        let mut segments = VecDeque::with_capacity(self.parts.len() * 3 + 1);
+        let mut len = Some(0_u64);
+        let mut add_len = |n: Option<u64>| len = len.zip(n).map(|(a, b)| a.saturating_add(b));
+        for part in self.parts {
+            let head = part.head(&boundary);
+            add_len(Some(head.len() as u64));
+            segments.push_back(Segment::Bytes(head));
+            match part.data {
+                PartData::Bytes(data) => {
+                    add_len(Some(data.len() as u64));
+                    segments.push_back(Segment::Bytes(data));
+                }
+                PartData::Reader { reader, len } => {
+                    add_len(len);
+                    segments.push_back(Segment::Reader {
+                        reader,
+                        remaining: len,
+                    });
+                }
+            }
+            add_len(Some(2));
+            segments.push_back(Segment::Bytes(Bytes::from_static(b"\r\n")));
+        }
+        let close = Bytes::from(format!("--{boundary}--\r\n").into_bytes());
+        add_len(Some(close.len() as u64));
+        segments.push_back(Segment::Bytes(close));
+
+        Ok(MultipartBody {
+            boundary,
+            segments,
+            len,
+            scratch: Vec::new(),
+        })
+    }
+}
+
+/// Returns true when the delimiter `--boundary` occurs in an in-memory part.
+fn collides(parts: &[PendingPart], boundary: &str) -> bool {
+    let delimiter = format!("--{boundary}");
+    let finder = memchr::memmem::Finder::new(delimiter.as_bytes());
+    parts.iter().any(|part| match &part.data {
+        PartData::Bytes(data) => finder.find(data).is_some(),
+        PartData::Reader { .. } => false,
+    })
+}
+
+fn generate_boundary() -> String {
+    let (hi, lo) = crate::cx::Cx::current().map_or_else(
+        || {
+            let mut rng = crate::util::DetRng::from_entropy();
+            (rng.next_u64(), rng.next_u64())
+        },
+        |cx| (cx.random_u64(), cx.random_u64()),
+    );
+    format!("asupersync-{hi:016x}{lo:016x}")
+}
+
+enum Segment {
+    Bytes(Bytes),
+    Reader {
+        reader: PartReader,
+        remaining: Option<u64>,
+    },
+}
+
+/// A streaming `multipart/form-data` body.
+///
+/// Built with [`MultipartBody::builder`]. Yields part heads and in-memory
+/// data as single frames and streamed parts in chunks of at most 64 KiB, so
+/// memory use is bounded by one chunk regardless of upload size.
+pub struct MultipartBody {
+    boundary: String,
+    segments: VecDeque<Segment>,
+    len: Option<u64>,
+    scratch: Vec<u8>,
+}
+
+impl fmt::Debug for MultipartBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultipartBody")
+            .field("boundary", &self.boundary)
+            .field("segments", &self.segments.len())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl MultipartBody {
+    /// Start building a multipart body.
+    #[must_use]
+    pub fn builder() -> MultipartBodyBuilder {
+        MultipartBodyBuilder {
+            boundary: None,
+            parts: Vec::new(),
+        }
+    }
+
+    /// The boundary separating parts.
+    #[must_use]
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The `Content-Type` header value announcing this body's boundary.
+    #[must_use]
+    pub fn content_type_header(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// The encoded length, when every part is sized.
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.len
+    }
+}
+
+impl Body for MultipartBody {
+    type Data = BytesCursor;
+    type Error = io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        loop {
+            let Some(segment) = this.segments.front_mut() else {
+                return Poll::Ready(None);
+            };
+            match segment {
+                Segment::Bytes(bytes) => {
+                    let bytes = std::mem::take(bytes);
+                    this.segments.pop_front();
+                    if !bytes.is_empty() {
+                        return Poll::Ready(Some(Ok(Frame::data(BytesCursor::new(bytes)))));
+                    }
+                }
+                Segment::Reader { remaining, .. } if *remaining == Some(0) => {
+                    this.segments.pop_front();
+                }
+                Segment::Reader { reader, remaining } => {
+                    let want = remaining.map_or(READ_CHUNK_SIZE, |left| {
+                        usize::try_from(left).map_or(READ_CHUNK_SIZE, |l| l.min(READ_CHUNK_SIZE))
+                    });
+                    this.scratch.resize(want, 0);
+                    let mut buf = ReadBuf::new(&mut this.scratch);
+                    match reader.as_mut().poll_read(cx, &mut buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => {
+                            this.segments.clear();
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        Poll::Ready(Ok(())) => {}
+                    }
+                    let n = buf.filled().len();
+                    if n == 0 {
+                        if remaining.is_some() {
+                            this.segments.clear();
+                            return Poll::Ready(Some(Err(io::Error::new(
+                                io::ErrorKind::UnexpectedEof,
+                                "multipart reader ended before its declared length",
+                            ))));
+                        }
+                        this.segments.pop_front();
+                        continue;
+                    }
+                    if let Some(left) = remaining {
+                        *left -= n as u64;
+                    }
+                    let chunk = Bytes::copy_from_slice(&this.scratch[..n]);
+                    return Poll::Ready(Some(Ok(Frame::data(BytesCursor::new(chunk)))));
+                }
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.len.map_or_else(SizeHint::default, SizeHint::with_exact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::bytes::Buf;
+    use std::task::Waker;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn collect(body: &mut MultipartBody) -> io::Result<Vec<u8>> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut out = Vec::new();
+        loop {
+            match Pin::new(&mut *body).poll_frame(&mut cx) {
+                Poll::Ready(Some(Ok(frame))) => {
+                    if let Some(mut data) = frame.into_data() {
+                        while data.has_remaining() {
+                            let chunk = data.chunk();
+                            out.extend_from_slice(chunk);
+                            data.advance(chunk.len());
+                        }
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => return Err(err),
+                Poll::Ready(None) => return Ok(out),
+                Poll::Pending => std::thread::yield_now(),
+            }
+        }
+    }
+
+    #[test]
+    fn sized_parts_report_exact_length() {
+        init_test("sized_parts_report_exact_length");
+        let mut body = MultipartBody::builder()
+            .boundary("b0und")
+            .expect("valid boundary")
+            .text("title", "hello")
+            .bytes("doc", "a \"b\".txt", "text/plain\r\nX: y", b"line\r\n".to_vec())
+            .reader("blob", "blob.bin", "application/octet-stream", &b"xyz"[..], Some(3))
+            .build()
+            .expect("build");
+        let declared = body.content_length();
+        let encoded = collect(&mut body).expect("encode");
+        crate::assert_with_log!(
+            declared == Some(encoded.len() as u64),
+            "content length matches encoding",
+            declared,
+            encoded.len()
+        );
+        let expected: &[u8] = b"--b0und\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\nhello\r\n\
+            --b0und\r\n\
+            Content-Disposition: form-data; name=\"doc\"; filename=\"a \\\"b\\\".txt\"\r\n\
+            Content-Type: text/plainX: y\r\n\r\nline\r\n\r\n\
+            --b0und\r\n\
+            Content-Disposition: form-data; name=\"blob\"; filename=\"blob.bin\"\r\n\
+            Content-Type: application/octet-stream\r\n\r\nxyz\r\n\
+            --b0und--\r\n";
+        crate::assert_with_log!(
+            encoded == expected,
+            "wire format",
+            String::from_utf8_lossy(expected),
+            String::from_utf8_lossy(&encoded)
+        );
+        crate::test_complete!("sized_parts_report_exact_length");
+    }
+
+    #[test]
+    fn unsized_reader_makes_body_chunked() {
+        init_test("unsized_reader_makes_body_chunked");
+        let body = MultipartBody::builder()
+            .text("a", "b")
+            .reader("f", "f.bin", "application/octet-stream", &b"data"[..], None)
+            .build()
+            .expect("build");
+        let hint = body.size_hint().exact();
+        crate::assert_with_log!(hint.is_none(), "no exact size", "None", hint);
+        crate::test_complete!("unsized_reader_makes_body_chunked");
+    }
+
+    #[test]
+    fn short_reader_fails_sized_part() {
+        init_test("short_reader_fails_sized_part");
+        let mut body = MultipartBody::builder()
+            .reader("f", "f.bin", "application/octet-stream", &b"abc"[..], Some(10))
+            .build()
+            .expect("build");
+        let err = collect(&mut body).expect_err("short reader");
+        crate::assert_with_log!(
+            err.kind() == io::ErrorKind::UnexpectedEof,
+            "unexpected eof",
+            io::ErrorKind::UnexpectedEof,
+            err.kind()
+        );
+        crate::test_complete!("short_reader_fails_sized_part");
+    }
+
+    #[test]
+    fn boundary_collision_is_rejected_or_redrawn() {
+        init_test("boundary_collision_is_rejected_or_redrawn");
+        let err = MultipartBody::builder()
+            .boundary("fixed")
+            .expect("valid boundary")
+            .text("evil", "prefix\r\n--fixed--\r\n")
+            .build()
+            .expect_err("collision");
+        crate::assert_with_log!(
+            err == MultipartError::BoundaryCollision,
+            "explicit boundary collision",
+            MultipartError::BoundaryCollision,
+            err
+        );
+
+        let body = MultipartBody::builder()
+            .text("a", "--asupersync-")
+            .build()
+            .expect("generated boundary");
+        let boundary = body.boundary().to_owned();
+        crate::assert_with_log!(
+            boundary.len() == 43 && boundary.starts_with("asupersync-"),
+            "128-bit generated boundary",
+            43,
+            boundary
+        );
+        let invalid = MultipartBody::builder().boundary("has space").err();
+        crate::assert_with_log!(
+            invalid == Some(MultipartError::InvalidBoundary),
+            "invalid boundary",
+            "InvalidBoundary",
+            invalid
+        );
+        crate::test_complete!("boundary_collision_is_rejected_or_redrawn");
+    }
+}
@@ -18,6 +18,7 @@
 
 use smallvec::SmallVec;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 use crate::types::Time;
@@ -31,6 +32,11 @@ pub struct PoolKey {
     pub port: u16,
     /// Whether this is an HTTPS connection.
     pub is_https: bool,
+    /// Backend address when the host is balanced across an endpoint set.
+    ///
+    /// Each endpoint gets its own sub-pool, so per-host limits apply per
+    /// endpoint.
+    pub endpoint: Option<SocketAddr>,
 }
 
 impl PoolKey {
@@ -41,9 +47,17 @@ impl PoolKey {
             host: host.into(),
             port,
             is_https,
+            endpoint: None,
         }
     }
 
+    /// Scopes this key to one balanced backend endpoint.
+    #[must_use]
+    pub const fn with_endpoint(mut self, endpoint: SocketAddr) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
     /// Creates a pool key for HTTP (port 80 default).
     #[must_use]
     pub fn http(host: impl Into<String>, port: Option<u16>) -> Self {
@@ -0,0 +1,168 @@
+//! Canonical request form, fingerprints and field diffs.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Protocol-independent canonical form of a request.
+///
+/// Fields are ordered `(key, value)` pairs; a key may repeat (query
+/// parameters, bind parameters). Volatile parts of the request (hop-by-hop
+/// headers, the `Host` the proxy was reached on) are left out by the
+/// [`WireProtocol`](super::WireProtocol) that builds it, so the same logical
+/// request canonicalizes identically at record and replay time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanonicalRequest {
+    /// Protocol name, e.g. `"http"`.
+    pub protocol: String,
+    /// Ordered fields.
+    pub fields: Vec<(String, String)>,
+}
+
+impl CanonicalRequest {
+    /// Creates an empty canonical request for `protocol`.
+    #[must_use]
+    pub fn new(protocol: impl Into<String>) -> Self {
+        Self {
+            protocol: protocol.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Appends a field.
+    pub fn push(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.fields.push((key.into(), value.into()));
+    }
+
+    /// Returns the first value of `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replaces every value of `key`, returning how many were replaced.
+    pub fn replace(&mut self, key: &str, value: &str) -> usize {
+        let mut replaced = 0;
+        for (_, existing) in self.fields.iter_mut().filter(|(k, _)| k == key) {
+            value.clone_into(existing);
+            replaced += 1;
+        }
+        replaced
+    }
+
+    /// Stable hex fingerprint of the protocol and every field.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        let mut update = |part: &str| {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        };
+        update(&self.protocol);
+        for (key, value) in &self.fields {
+            update(key);
+            update(value);
+        }
+        hex::encode(&hasher.finalize()[..16])
+    }
+
+    /// Field-by-field differences from `recorded` to `self`.
+    ///
+    /// Fields are paired by key and occurrence, so the second `query.id`
+    /// is compared with the second recorded `query.id`.
+    #[must_use]
+    pub fn diff(&self, recorded: &Self) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        if self.protocol != recorded.protocol {
+            diffs.push(FieldDiff::Changed {
+                key: "protocol".to_string(),
+                recorded: recorded.protocol.clone(),
+                actual: self.protocol.clone(),
+            });
+        }
+        let actual = occurrences(&self.fields);
+        let expected = occurrences(&recorded.fields);
+        for (slot, recorded_value) in &expected {
+            match actual.get(slot) {
+                Some(value) if value == recorded_value => {}
+                Some(value) => diffs.push(FieldDiff::Changed {
+                    key: slot.0.to_string(),
+                    recorded: (*recorded_value).to_string(),
+                    actual: (*value).to_string(),
+                }),
+                None => diffs.push(FieldDiff::Missing {
+                    key: slot.0.to_string(),
+                    recorded: (*recorded_value).to_string(),
+                }),
+            }
+        }
+        for (slot, value) in &actual {
+            if !expected.contains_key(slot) {
+                diffs.push(FieldDiff::Unexpected {
+                    key: slot.0.to_string(),
+                    actual: (*value).to_string(),
+                });
+            }
+        }
+        diffs
+    }
+}
+
+fn occurrences(fields: &[(String, String)]) -> BTreeMap<(&str, usize), &str> {
+    let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+    fields
+        .iter()
+        .map(|(key, value)| {
+            let nth = seen.entry(key.as_str()).or_default();
+            let slot = (key.as_str(), *nth);
+            *nth += 1;
+            (slot, value.as_str())
+        })
+        .collect()
+}
+
+/// One difference between a replayed request and a recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDiff {
+    /// Both requests have the field with different values.
+    Changed {
+        /// Field key.
+        key: String,
+        /// Recorded value.
+        recorded: String,
+        /// Replayed value.
+        actual: String,
+    },
+    /// The recorded request has a field the replayed one lacks.
+    Missing {
+        /// Field key.
+        key: String,
+        /// Recorded value.
+        recorded: String,
+    },
+    /// The replayed request has a field the recorded one lacks.
+    Unexpected {
+        /// Field key.
+        key: String,
+        /// Replayed value.
+        actual: String,
+    },
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Changed {
+                key,
+                recorded,
+                actual,
+            } => write!(f, "- {key}: {recorded:?}\n+ {key}: {actual:?}"),
+            Self::Missing { key, recorded } => write!(f, "- {key}: {recorded:?}"),
+            Self::Unexpected { key, actual } => write!(f, "+ {key}: {actual:?}"),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Versioned, streamable cassette file format.
+//!
+//! A cassette is JSON lines. The first line is the header
+//! `{"format":"asupersync-cassette","version":N}`; every following non-empty
+//! line is one [`Interaction`]. Writers append and flush one line per
+//! completed exchange, so a recording interrupted mid-run still loads.
+//!
+//! Readers accept every version up to [`CASSETTE_VERSION`] and ignore fields
+//! they do not know, so additive changes stay within a version.
+
+use super::CassetteError;
+use super::fingerprint::CanonicalRequest;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Value of the header's `format` field.
+pub const CASSETTE_FORMAT: &str = "asupersync-cassette";
+
+/// Newest cassette version written and read by this build.
+pub const CASSETTE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+/// One response frame with its offset from the end of the request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseFrame {
+    /// Protocol-specific label, e.g. `"head"` or a Postgres message tag.
+    pub label: String,
+    /// Nanoseconds from the end of the request until the frame had arrived.
+    pub offset_ns: u64,
+    /// Frame bytes.
+    #[serde(serialize_with = "encode_base64", deserialize_with = "decode_base64")]
+    pub data: Vec<u8>,
+}
+
+impl ResponseFrame {
+    /// Offset from the end of the request as a duration.
+    #[must_use]
+    pub const fn offset(&self) -> Duration {
+        Duration::from_nanos(self.offset_ns)
+    }
+}
+
+/// One recorded request and its response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Fingerprint of `request` (after scrubbing).
+    pub fingerprint: String,
+    /// Canonical request.
+    pub request: CanonicalRequest,
+    /// Nanoseconds from the start of the recording until the request completed.
+    pub started_ns: u64,
+    /// Response frames in arrival order; empty if the request has no response.
+    pub frames: Vec<ResponseFrame>,
+}
+
+impl Interaction {
+    /// Offset of the last response frame, i.e. the recorded response latency.
+    #[must_use]
+    pub fn latency(&self) -> Duration {
+        self.frames
+            .last()
+            .map_or(Duration::ZERO, ResponseFrame::offset)
+    }
+}
+
+fn encode_base64<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+fn decode_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(serde::de::Error::custom)
+}
+
+/// A fully loaded cassette.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cassette {
+    /// Version the cassette was written with.
+    pub version: u32,
+    /// Interactions in recording order.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Loads every interaction from the cassette at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let file = File::open(path)?;
+        Self::read(BufReader::new(file))
+    }
+
+    /// Loads every interaction from `reader`.
+    pub fn read(reader: impl BufRead) -> Result<Self, CassetteError> {
+        let reader = CassetteReader::new(reader)?;
+        let version = reader.version();
+        let interactions = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            version,
+            interactions,
+        })
+    }
+
+    /// Parses a cassette held in memory.
+    pub fn parse(text: &str) -> Result<Self, CassetteError> {
+        Self::read(text.as_bytes())
+    }
+
+    /// Serializes the cassette in the current format version.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut writer = CassetteWriter::new(Vec::new()).expect("writing to memory");
+        for interaction in &self.interactions {
+            writer.append(interaction).expect("writing to memory");
+        }
+        String::from_utf8(writer.into_inner()).expect("cassette is UTF-8")
+    }
+}
+
+/// Appends interactions to a cassette, one flushed line each.
+#[derive(Debug)]
+pub struct CassetteWriter<W: Write> {
+    out: W,
+    written: usize,
+}
+
+impl CassetteWriter<BufWriter<File>> {
+    /// Creates (truncating) a cassette file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CassetteWriter<W> {
+    /// Writes the header to `out`.
+    pub fn new(mut out: W) -> Result<Self, CassetteError> {
+        let header = Header {
+            format: CASSETTE_FORMAT.to_string(),
+            version: CASSETTE_VERSION,
+        };
+        write_line(&mut out, &header)?;
+        Ok(Self { out, written: 0 })
+    }
+
+    /// Appends one interaction and flushes it.
+    pub fn append(&mut self, interaction: &Interaction) -> Result<(), CassetteError> {
+        write_line(&mut self.out, interaction)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of interactions written.
+    #[must_use]
+    pub const fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+fn write_line<W: Write, T: Serialize>(out: &mut W, value: &T) -> Result<(), CassetteError> {
+    serde_json::to_writer(&mut *out, value).map_err(std::io::Error::from)?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Streams interactions out of a cassette without loading it whole.
+#[derive(Debug)]
+pub struct CassetteReader<R> {
+    input: R,
+    version: u32,
+    line: usize,
+}
+
+impl<R: BufRead> CassetteReader<R> {
+    /// Reads and validates the header.
+    pub fn new(mut input: R) -> Result<Self, CassetteError> {
+        let mut text = String::new();
+        input.read_line(&mut text)?;
+        let header: Header = serde_json::from_str(&text).map_err(|err| CassetteError::Format {
+            line: 1,
+            message: err.to_string(),
+        })?;
+        if header.format != CASSETTE_FORMAT {
+            return Err(CassetteError::Format {
+                line: 1,
+                message: format!("expected format {CASSETTE_FORMAT:?}, found {:?}", header.format),
+            });
+        }
+        if header.version > CASSETTE_VERSION {
+            return Err(CassetteError::UnsupportedVersion {
+                found: header.version,
+                supported: CASSETTE_VERSION,
+            });
+        }
+        Ok(Self {
+            input,
+            version: header.version,
+            line: 1,
+        })
+    }
+
+    /// Version from the header.
+    #[must_use]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+impl<R: BufRead> Iterator for CassetteReader<R> {
+    type Item = Result<Interaction, CassetteError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            self.line += 1;
+            match self.input.read_line(&mut text) {
+                Ok(0) => return None,
+                Ok(_) if text.trim().is_empty() => {}
+                Ok(_) => break,
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+        let line = self.line;
+        Some(
+            serde_json::from_str(&text).map_err(|err| CassetteError::Format {
+                line,
+                message: err.to_string(),
+            }),
+        )
+    }
+}
@@ -0,0 +1,517 @@
+//! Record/replay of external network dependencies for lab tests.
+//!
+//! Code that talks to a real vendor HTTP API or a Postgres server cannot run
+//! deterministically inside the lab. This module records those conversations
+//! once on the native runtime and serves them back under virtual time:
+//!
+//! ```text
+//!  record (native)                          replay (lab)
+//!  client ──▶ RecordingProxy ──▶ upstream   client ──▶ ReplayServer
+//!                  │                                       ▲
+//!                  └──────────▶ cassette ──────────────────┘
+//! ```
+//!
+//! - [`RecordingProxy`] sits between the client and the real dependency,
+//!   forwarding bytes unchanged. A [`WireProtocol`] cuts the byte streams into
+//!   request and response frames ([`HttpProtocol`], [`PostgresProtocol`]);
+//!   each exchange is stored as an [`Interaction`] keyed by the fingerprint of
+//!   its [`CanonicalRequest`], with every response frame stamped by its offset
+//!   from the end of the request.
+//! - [`ReplayServer`] loads the cassette and answers matching requests,
+//!   releasing each frame at its recorded offset on the lab's virtual clock.
+//!   A request without a recorded match fails with a [`ReplayMismatch`] that
+//!   diffs it against the nearest recorded request.
+//! - [`Scrubber`]s run at record time to redact secrets from requests and
+//!   responses. The replay server applies the same scrubbers to incoming
+//!   requests, so redacted fields still match.
+//!
+//! Cassettes are versioned JSON lines (see [`CASSETTE_VERSION`]): a header line
+//! followed by one interaction per line, written as each exchange completes and
+//! readable incrementally with [`CassetteReader`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Native, once: record against the real API.
+//! let proxy = RecordingProxy::new(HttpProtocol::new(), CassetteWriter::create(path)?)
+//!     .with_scrubber(RedactFields::new(["header.authorization"]));
+//! proxy.serve_connection(&cx, client_side, upstream).await?;
+//!
+//! // Lab, every run: replay under virtual time.
+//! let server = ReplayServer::new(Cassette::load(path)?, HttpProtocol::new())
+//!     .with_scrubber(RedactFields::new(["header.authorization"]));
+//! server.serve_connection(&cx, server_side).await?;
+//! ```
+
+mod fingerprint;
+mod format;
+mod protocol;
+mod proxy;
+mod replay;
+mod scrub;
+
+pub use fingerprint::{CanonicalRequest, FieldDiff};
+pub use format::{
+    CASSETTE_FORMAT, CASSETTE_VERSION, Cassette, CassetteReader, CassetteWriter, Interaction,
+    ResponseFrame,
+};
+pub use protocol::{HttpProtocol, PostgresProtocol, WireProtocol};
+pub use proxy::RecordingProxy;
+pub use replay::{ReplayMismatch, ReplayResponse, ReplayServer};
+pub use scrub::{MaskResponseText, REDACTED, RedactFields, Scrubber};
+
+use thiserror::Error;
+
+/// Errors raised while recording or replaying a cassette.
+#[derive(Debug, Error)]
+pub enum CassetteError {
+    /// Reading or writing the cassette or a connection failed.
+    #[error("cassette I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// A cassette line could not be parsed.
+    #[error("malformed cassette line {line}: {message}")]
+    Format {
+        /// 1-based line number.
+        line: usize,
+        /// Parser message.
+        message: String,
+    },
+    /// The cassette was written by a newer format version.
+    #[error("cassette version {found} is newer than supported version {supported}")]
+    UnsupportedVersion {
+        /// Version found in the header.
+        found: u32,
+        /// Newest version this build reads.
+        supported: u32,
+    },
+    /// Bytes on the wire did not parse as the configured protocol.
+    #[error("{protocol} framing error: {message}")]
+    Protocol {
+        /// Protocol name.
+        protocol: &'static str,
+        /// What was wrong.
+        message: String,
+    },
+    /// A replayed request matched no recorded interaction.
+    #[error("{0}")]
+    Mismatch(Box<ReplayMismatch>),
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Cx;
+    use crate::io::{AsyncReadExt, AsyncWriteExt};
+    use crate::lab::runtime::LabRuntime;
+    use crate::net::tcp::virtual_tcp::VirtualTcpStream;
+    use crate::types::{Budget, Time};
+    use parking_lot::Mutex;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    const TICK_NS: u64 = 7_000_000;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    std::thread_local! {
+        static TICKS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Clock that advances one tick per reading.
+    fn ticking_time() -> Time {
+        TICKS.with(|ticks| {
+            let now = ticks.get();
+            ticks.set(now + TICK_NS);
+            Time::from_nanos(now)
+        })
+    }
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn http_request(method: &str, target: &str, headers: &[(&str, &str)], body: &str) -> Vec<u8> {
+        let mut request = format!("{method} {target} HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n");
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str(&format!("Content-Length: {}\r\n\r\n{body}", body.len()));
+        request.into_bytes()
+    }
+
+    fn http_response(body: &str) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .into_bytes()
+    }
+
+    fn balance_request(token: &str) -> Vec<u8> {
+        let auth = format!("Bearer {token}");
+        http_request("GET", "/v1/balance?currency=usd", &[("Authorization", &auth)], "")
+    }
+
+    fn charge_request(amount: u32) -> Vec<u8> {
+        let body = format!("{{\"currency\":\"usd\",\"amount\":{amount}}}");
+        http_request(
+            "POST",
+            "/v1/charges",
+            &[("Content-Type", "application/json")],
+            &body,
+        )
+    }
+
+    struct Recording {
+        cassette: String,
+        client_received: Vec<u8>,
+        upstream_received: Vec<u8>,
+    }
+
+    /// Records `exchanges` through a proxy on the native runtime, with a
+    /// scripted upstream whose responses are already buffered.
+    fn record(exchanges: Vec<(Vec<u8>, Vec<u8>)>) -> Recording {
+        let result = Arc::new(Mutex::new(None));
+        let out = Arc::clone(&result);
+        crate::test_utils::run_test_with_cx(|cx| async move {
+            let (mut app, proxy_client) = VirtualTcpStream::pair(addr(40_000), addr(9000));
+            let (proxy_upstream, mut upstream) = VirtualTcpStream::pair(addr(40_001), addr(443));
+            for (request, response) in &exchanges {
+                app.write_all(request).await.expect("client write");
+                upstream.write_all(response).await.expect("upstream write");
+            }
+            app.shutdown().await.expect("client shutdown");
+
+            let writer = CassetteWriter::new(Vec::new()).expect("cassette header");
+            let proxy = RecordingProxy::new(HttpProtocol::new(), writer)
+                .with_scrubber(RedactFields::new(["header.authorization"]))
+                .with_scrubber(MaskResponseText::new(["tok_live_4242"]))
+                .with_time_getter(ticking_time);
+            let recorded = proxy
+                .serve_connection(&cx, proxy_client, proxy_upstream)
+                .await
+                .expect("proxy connection");
+            assert_eq!(recorded, exchanges.len());
+
+            let mut client_received = Vec::new();
+            app.read_to_end(&mut client_received).await.expect("client read");
+            let mut upstream_received = Vec::new();
+            upstream
+                .read_to_end(&mut upstream_received)
+                .await
+                .expect("upstream read");
+            let cassette = String::from_utf8(proxy.into_inner()).expect("UTF-8 cassette");
+            *out.lock() = Some(Recording {
+                cassette,
+                client_received,
+                upstream_received,
+            });
+        });
+        let recording = result.lock().take();
+        recording.expect("recording finished")
+    }
+
+    fn standard_exchanges() -> Vec<(Vec<u8>, Vec<u8>)> {
+        vec![
+            (
+                balance_request("sk_live_original"),
+                http_response("{\"available\":100}"),
+            ),
+            (
+                charge_request(42),
+                http_response("{\"id\":\"ch_1\",\"token\":\"tok_live_4242\"}"),
+            ),
+        ]
+    }
+
+    /// Replays `requests` on the lab runtime, returning each response with
+    /// the virtual time it completed at.
+    fn replay_in_lab(cassette: &Cassette, requests: &[Vec<u8>], seed: u64) -> Vec<(Time, Vec<u8>)> {
+        let mut runtime = LabRuntime::with_seed(seed);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let server = Arc::new(
+            ReplayServer::new(cassette.clone(), HttpProtocol::new())
+                .with_scrubber(RedactFields::new(["header.authorization"])),
+        );
+        let (mut client, server_side) = VirtualTcpStream::pair(addr(40_002), addr(9000));
+        let transcript = Arc::new(Mutex::new(Vec::new()));
+
+        let serving = Arc::clone(&server);
+        let (task, _server_handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("lab cx");
+                serving
+                    .serve_connection(&cx, server_side)
+                    .await
+                    .expect("replayed connection");
+            })
+            .expect("create server task");
+        runtime.scheduler.lock().schedule(task, 0);
+
+        let requests = requests.to_vec();
+        let log = Arc::clone(&transcript);
+        let (task, _client_handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("lab cx");
+                let protocol = HttpProtocol::new();
+                let mut chunk = [0_u8; 1024];
+                for request in requests {
+                    client.write_all(&request).await.expect("request write");
+                    let mut response: Vec<u8> = Vec::new();
+                    loop {
+                        let len = protocol
+                            .response_len(&request, &response, false)
+                            .expect("response framing");
+                        if len.is_some() {
+                            break;
+                        }
+                        let read = client.read(&mut chunk).await.expect("response read");
+                        response.extend_from_slice(&chunk[..read]);
+                    }
+                    log.lock().push((cx.now(), response));
+                }
+                client.shutdown().await.expect("client shutdown");
+            })
+            .expect("create client task");
+        runtime.scheduler.lock().schedule(task, 0);
+
+        let _report = runtime.run_with_auto_advance();
+        let transcript = transcript.lock().clone();
+        assert!(server.mismatches().is_empty());
+        transcript
+    }
+
+    #[test]
+    fn record_then_replay_round_trip_is_deterministic_across_seeds() {
+        init_test("record_then_replay_round_trip_is_deterministic_across_seeds");
+        let exchanges = standard_exchanges();
+        let recording = record(exchanges.clone());
+        let requests: Vec<Vec<u8>> = exchanges.iter().map(|(request, _)| request.clone()).collect();
+        crate::assert_with_log!(
+            recording.upstream_received == requests.concat(),
+            "proxy forwards requests unchanged",
+            requests.concat().len(),
+            recording.upstream_received.len()
+        );
+        let cassette = Cassette::parse(&recording.cassette).expect("recorded cassette parses");
+        crate::assert_with_log!(
+            cassette.interactions.len() == 2,
+            "one interaction per exchange",
+            2,
+            cassette.interactions.len()
+        );
+
+        let baseline = replay_in_lab(&cassette, &requests, 1);
+        for seed in [7, 42, 0xDEAD_BEEF] {
+            let transcript = replay_in_lab(&cassette, &requests, seed);
+            crate::assert_with_log!(
+                transcript == baseline,
+                "replay transcript identical across seeds",
+                seed,
+                transcript.len()
+            );
+        }
+        let replayed: Vec<u8> = baseline
+            .iter()
+            .flat_map(|(_, response)| response.clone())
+            .collect();
+        // The charge response was masked at record time; the balance one is verbatim.
+        crate::assert_with_log!(
+            baseline[0].1 == exchanges[0].1,
+            "replayed response matches the recorded one",
+            exchanges[0].1.len(),
+            baseline[0].1.len()
+        );
+        crate::assert_with_log!(
+            replayed.len() == recording.client_received.len(),
+            "replay serves as many bytes as the proxy forwarded",
+            recording.client_received.len(),
+            replayed.len()
+        );
+        crate::test_complete!("record_then_replay_round_trip_is_deterministic_across_seeds");
+    }
+
+    #[test]
+    fn recorded_latency_maps_onto_virtual_time() {
+        init_test("recorded_latency_maps_onto_virtual_time");
+        let cassette = Cassette::parse(include_str!("fixtures/http_v1.jsonl")).expect("fixture");
+        let request = http_request("GET", "/v1/balance", &[("Accept", "application/json")], "");
+        let server = ReplayServer::new(cassette.clone(), HttpProtocol::new());
+        let response = server.respond(&request).expect("fixture request matches");
+        let schedule: Vec<Time> = response
+            .schedule(Time::from_millis(100))
+            .into_iter()
+            .map(|(due, _)| due)
+            .collect();
+        crate::assert_with_log!(
+            schedule == [Time::from_millis(104), Time::from_millis(109)],
+            "frames released at recorded offsets",
+            "[104ms, 109ms]",
+            format!("{schedule:?}")
+        );
+
+        // Two requests back to back on the lab clock: 9ms each.
+        let transcript = replay_in_lab(&cassette, &[request.clone(), request], 3);
+        let times: Vec<Time> = transcript.iter().map(|(at, _)| *at).collect();
+        crate::assert_with_log!(
+            times == [Time::from_millis(9), Time::from_millis(18)],
+            "responses complete at recorded latency in virtual time",
+            "[9ms, 18ms]",
+            format!("{times:?}")
+        );
+        crate::test_complete!("recorded_latency_maps_onto_virtual_time");
+    }
+
+    #[test]
+    fn fingerprint_mismatch_reports_diff_against_nearest_request() {
+        init_test("fingerprint_mismatch_reports_diff_against_nearest_request");
+        let recording = record(standard_exchanges());
+        let cassette = Cassette::parse(&recording.cassette).expect("recorded cassette parses");
+        let server = ReplayServer::new(cassette, HttpProtocol::new())
+            .with_scrubber(RedactFields::new(["header.authorization"]));
+
+        let err = server
+            .respond(&charge_request(43))
+            .expect_err("different amount must not match");
+        let CassetteError::Mismatch(mismatch) = &err else {
+            panic!("expected a mismatch, got {err}");
+        };
+        crate::assert_with_log!(
+            mismatch.diff
+                == [FieldDiff::Changed {
+                    key: "body".to_string(),
+                    recorded: "{\"amount\":42,\"currency\":\"usd\"}".to_string(),
+                    actual: "{\"amount\":43,\"currency\":\"usd\"}".to_string(),
+                }],
+            "diff names only the changed field",
+            "body 42 -> 43",
+            format!("{:?}", mismatch.diff)
+        );
+        let message = err.to_string();
+        crate::assert_with_log!(
+            message.contains("nearest recorded request")
+                && message.contains("- body: \"{\\\"amount\\\":42")
+                && message.contains("+ body: \"{\\\"amount\\\":43"),
+            "message shows the diff",
+            "diff lines",
+            message
+        );
+        crate::assert_with_log!(
+            server.mismatches().len() == 1 && server.unused().len() == 2,
+            "mismatch recorded and nothing served",
+            "1 mismatch, 2 unused",
+            format!("{} / {}", server.mismatches().len(), server.unused().len())
+        );
+        crate::test_complete!("fingerprint_mismatch_reports_diff_against_nearest_request");
+    }
+
+    #[test]
+    fn scrubbing_redacts_secrets_and_still_matches() {
+        init_test("scrubbing_redacts_secrets_and_still_matches");
+        let recording = record(standard_exchanges());
+        crate::assert_with_log!(
+            !recording.cassette.contains("sk_live_original"),
+            "authorization header redacted in the cassette",
+            false,
+            recording.cassette.contains("sk_live_original")
+        );
+        let cassette = Cassette::parse(&recording.cassette).expect("recorded cassette parses");
+        let balance = &cassette.interactions[0];
+        crate::assert_with_log!(
+            balance.request.get("header.authorization") == Some(REDACTED),
+            "redacted field kept with placeholder",
+            REDACTED,
+            format!("{:?}", balance.request.get("header.authorization"))
+        );
+        let charge = cassette.interactions[1]
+            .frames
+            .iter()
+            .flat_map(|frame| frame.data.clone())
+            .collect::<Vec<u8>>();
+        let charge = String::from_utf8(charge).expect("UTF-8 response");
+        crate::assert_with_log!(
+            !charge.contains("tok_live_4242") && charge.contains("*************"),
+            "response secret masked with same length",
+            "masked",
+            charge
+        );
+        // The client still saw the real response while recording.
+        let forwarded = String::from_utf8_lossy(&recording.client_received).into_owned();
+        crate::assert_with_log!(
+            forwarded.contains("tok_live_4242"),
+            "scrubbing does not alter forwarded bytes",
+            true,
+            forwarded.contains("tok_live_4242")
+        );
+
+        let server = ReplayServer::new(cassette, HttpProtocol::new())
+            .with_scrubber(RedactFields::new(["header.authorization"]));
+        let replayed = server.respond(&balance_request("sk_live_rotated"));
+        crate::assert_with_log!(
+            replayed.is_ok(),
+            "a different token matches the redacted recording",
+            true,
+            replayed.is_ok()
+        );
+        crate::test_complete!("scrubbing_redacts_secrets_and_still_matches");
+    }
+
+    #[test]
+    fn checked_in_v1_cassette_still_loads_and_replays() {
+        init_test("checked_in_v1_cassette_still_loads_and_replays");
+        let cassette = Cassette::parse(include_str!("fixtures/http_v1.jsonl")).expect("v1 fixture");
+        crate::assert_with_log!(cassette.version == 1, "fixture version", 1, cassette.version);
+        let interaction = &cassette.interactions[0];
+        crate::assert_with_log!(
+            interaction.request.fingerprint() == interaction.fingerprint,
+            "fingerprint algorithm unchanged since v1",
+            &interaction.fingerprint,
+            interaction.request.fingerprint()
+        );
+        crate::assert_with_log!(
+            interaction.latency() == std::time::Duration::from_millis(9),
+            "latency",
+            "9ms",
+            interaction.latency()
+        );
+
+        let request = http_request("GET", "/v1/balance", &[("Accept", "application/json")], "");
+        let server = ReplayServer::new(cassette.clone(), HttpProtocol::new());
+        let response = server.respond(&request).expect("v1 request matches");
+        crate::assert_with_log!(
+            response.bytes().ends_with(b"{\"available\":100}"),
+            "v1 response body",
+            "{\"available\":100}",
+            String::from_utf8_lossy(&response.bytes())
+        );
+
+        // Re-serializing in the current format round-trips losslessly.
+        let rewritten = Cassette::parse(&cassette.to_text()).expect("rewritten cassette");
+        crate::assert_with_log!(
+            rewritten.interactions == cassette.interactions,
+            "current writer preserves v1 content",
+            1,
+            rewritten.interactions.len()
+        );
+        let newer = "{\"format\":\"asupersync-cassette\",\"version\":99}\n";
+        let rejected = matches!(
+            Cassette::parse(newer),
+            Err(CassetteError::UnsupportedVersion { found: 99, .. })
+        );
+        crate::assert_with_log!(rejected, "newer versions rejected", true, rejected);
+        crate::test_complete!("checked_in_v1_cassette_still_loads_and_replays");
+    }
+}
@@ -0,0 +1,592 @@
+//! Request/response framing and canonicalization per wire protocol.
+
+use super::CassetteError;
+use super::fingerprint::CanonicalRequest;
+use base64::Engine;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Frames one protocol's byte streams into requests and responses.
+///
+/// Connections are assumed to be request/response: the client sends one
+/// complete request and waits for the complete response before sending the
+/// next (no pipelining).
+pub trait WireProtocol: fmt::Debug + Send + Sync {
+    /// Protocol name stored in canonical requests.
+    fn name(&self) -> &'static str;
+
+    /// Length of the complete request at the start of `buf`, or `None` if
+    /// more bytes are needed.
+    fn request_len(&self, buf: &[u8]) -> Result<Option<usize>, CassetteError>;
+
+    /// Length of the complete response to `request` at the start of `buf`,
+    /// or `None` if more bytes are needed. `eof` is set once the upstream
+    /// closed its side.
+    fn response_len(
+        &self,
+        request: &[u8],
+        buf: &[u8],
+        eof: bool,
+    ) -> Result<Option<usize>, CassetteError>;
+
+    /// Splits a complete response into labelled frames, returned as
+    /// `(label, end offset)` pairs in order.
+    fn split_response(&self, response: &[u8]) -> Vec<(String, usize)>;
+
+    /// Canonical form of a complete request.
+    fn canonicalize(&self, request: &[u8]) -> Result<CanonicalRequest, CassetteError>;
+}
+
+fn display_bytes(bytes: &[u8]) -> String {
+    std::str::from_utf8(bytes).map_or_else(
+        |_| {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+            format!("base64:{encoded}")
+        },
+        str::to_string,
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// ─── HTTP/1.x ───────────────────────────────────────────────────────────────
+
+/// Headers left out of the canonical HTTP request by default.
+const DEFAULT_IGNORED_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "date",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// HTTP/1.x framing.
+///
+/// The canonical request holds the method, path, sorted query parameters,
+/// sorted headers (minus hop-by-hop and volatile ones such as `Host`) and the
+/// de-chunked body; JSON bodies are canonicalized with sorted object keys.
+/// Interim (`1xx`) responses are not supported.
+#[derive(Debug, Clone)]
+pub struct HttpProtocol {
+    ignored_headers: BTreeSet<String>,
+}
+
+impl Default for HttpProtocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpProtocol {
+    /// HTTP framing with the default ignored headers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ignored_headers: DEFAULT_IGNORED_HEADERS
+                .iter()
+                .map(|name| (*name).to_string())
+                .collect(),
+        }
+    }
+
+    /// Leaves `name` out of canonical requests, e.g. a per-request id.
+    #[must_use]
+    pub fn ignore_header(mut self, name: &str) -> Self {
+        self.ignored_headers.insert(name.to_ascii_lowercase());
+        self
+    }
+}
+
+struct HttpHead {
+    start_line: String,
+    headers: Vec<(String, String)>,
+    len: usize,
+}
+
+impl HttpHead {
+    fn parse(buf: &[u8]) -> Result<Option<Self>, CassetteError> {
+        let Some(end) = find(buf, b"\r\n\r\n") else {
+            return Ok(None);
+        };
+        let text = std::str::from_utf8(&buf[..end])
+            .map_err(|_| http_error("non-UTF-8 head"))?;
+        let mut lines = text.split("\r\n");
+        let start_line = lines.next().unwrap_or_default().to_string();
+        let mut headers = Vec::new();
+        for line in lines {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| http_error(format!("malformed header {line:?}")))?;
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+        Ok(Some(Self {
+            start_line,
+            headers,
+            len: end + 4,
+        }))
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn is_chunked(&self) -> bool {
+        self.header("transfer-encoding")
+            .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"))
+    }
+
+    fn content_length(&self) -> Result<Option<usize>, CassetteError> {
+        self.header("content-length")
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| http_error(format!("invalid content-length {value:?}")))
+            })
+            .transpose()
+    }
+
+    /// Length of head plus body, if the body is delimited by the head.
+    fn message_len(&self, buf: &[u8]) -> Result<Option<usize>, CassetteError> {
+        if self.is_chunked() {
+            return Ok(chunked_len(&buf[self.len..])?.map(|body| self.len + body));
+        }
+        let body = self.content_length()?.unwrap_or(0);
+        Ok((buf.len() >= self.len + body).then_some(self.len + body))
+    }
+}
+
+fn http_error(message: impl Into<String>) -> CassetteError {
+    CassetteError::Protocol {
+        protocol: "http",
+        message: message.into(),
+    }
+}
+
+/// Parses one chunk-size line at `at`, returning the size and the offset of
+/// the chunk data.
+fn chunk_header(body: &[u8], at: usize) -> Result<Option<(usize, usize)>, CassetteError> {
+    let Some(line_end) = body.get(at..).and_then(|rest| find(rest, b"\r\n")) else {
+        return Ok(None);
+    };
+    let line = std::str::from_utf8(&body[at..at + line_end])
+        .map_err(|_| http_error("non-UTF-8 chunk size"))?;
+    let size = line.split(';').next().unwrap_or_default().trim();
+    let size = usize::from_str_radix(size, 16)
+        .map_err(|_| http_error(format!("invalid chunk size {size:?}")))?;
+    Ok(Some((size, at + line_end + 2)))
+}
+
+/// Length of a complete chunked body (including trailers).
+fn chunked_len(body: &[u8]) -> Result<Option<usize>, CassetteError> {
+    let mut at = 0;
+    loop {
+        let Some((size, data)) = chunk_header(body, at)? else {
+            return Ok(None);
+        };
+        if size == 0 {
+            if body[data..].starts_with(b"\r\n") {
+                return Ok(Some(data + 2));
+            }
+            return Ok(find(&body[data..], b"\r\n\r\n").map(|end| data + end + 4));
+        }
+        at = data + size + 2;
+        if body.len() < at {
+            return Ok(None);
+        }
+    }
+}
+
+/// Concatenated chunk data of a complete chunked body.
+fn dechunk(body: &[u8]) -> Result<Vec<u8>, CassetteError> {
+    let mut data = Vec::new();
+    let mut at = 0;
+    while let Some((size, start)) = chunk_header(body, at)? {
+        if size == 0 || body.len() < start + size {
+            break;
+        }
+        data.extend_from_slice(&body[start..start + size]);
+        at = start + size + 2;
+    }
+    Ok(data)
+}
+
+/// Rebuilds JSON objects with sorted keys, whatever the map's ordering.
+fn sort_json(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_json(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_json).collect())
+        }
+        other => other,
+    }
+}
+
+impl WireProtocol for HttpProtocol {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn request_len(&self, buf: &[u8]) -> Result<Option<usize>, CassetteError> {
+        HttpHead::parse(buf)?.map_or(Ok(None), |head| head.message_len(buf))
+    }
+
+    fn response_len(
+        &self,
+        request: &[u8],
+        buf: &[u8],
+        eof: bool,
+    ) -> Result<Option<usize>, CassetteError> {
+        let Some(head) = HttpHead::parse(buf)? else {
+            return Ok((eof && !buf.is_empty()).then_some(buf.len()));
+        };
+        let status = head
+            .start_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| http_error(format!("malformed status line {:?}", head.start_line)))?;
+        let head_request = request.starts_with(b"HEAD ");
+        if head_request || status == 204 || status == 304 || (100..200).contains(&status) {
+            return Ok(Some(head.len));
+        }
+        if head.is_chunked() || head.header("content-length").is_some() {
+            let len = head.message_len(buf)?;
+            return Ok(len.or_else(|| eof.then_some(buf.len())));
+        }
+        // Close-delimited body.
+        Ok(eof.then_some(buf.len()))
+    }
+
+    fn split_response(&self, response: &[u8]) -> Vec<(String, usize)> {
+        let head = find(response, b"\r\n\r\n").map_or(response.len(), |end| end + 4);
+        let mut frames = vec![("head".to_string(), head)];
+        if response.len() > head {
+            frames.push(("body".to_string(), response.len()));
+        }
+        frames
+    }
+
+    fn canonicalize(&self, request: &[u8]) -> Result<CanonicalRequest, CassetteError> {
+        let head = HttpHead::parse(request)?
+            .ok_or_else(|| http_error("incomplete request"))?;
+        let mut parts = head.start_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or_default();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let mut canonical = CanonicalRequest::new(self.name());
+        canonical.push("method", method);
+        canonical.push("path", path);
+        let mut params: Vec<(&str, &str)> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .collect();
+        params.sort_unstable();
+        for (name, value) in params {
+            canonical.push(format!("query.{name}"), value);
+        }
+        let mut headers: Vec<&(String, String)> = head
+            .headers
+            .iter()
+            .filter(|(name, _)| !self.ignored_headers.contains(name))
+            .collect();
+        headers.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, value) in headers {
+            canonical.push(format!("header.{name}"), value.as_str());
+        }
+
+        let raw = &request[head.len..];
+        let body = if head.is_chunked() {
+            dechunk(raw)?
+        } else {
+            raw.to_vec()
+        };
+        if !body.is_empty() {
+            let json = head
+                .header("content-type")
+                .is_some_and(|value| value.contains("json"));
+            let value = json
+                .then(|| serde_json::from_slice::<serde_json::Value>(&body).ok())
+                .flatten()
+                .map_or_else(|| display_bytes(&body), |value| sort_json(value).to_string());
+            canonical.push("body", value);
+        }
+        Ok(canonical)
+    }
+}
+
+// ─── PostgreSQL ─────────────────────────────────────────────────────────────
+
+const PG_SSL_REQUEST: i32 = 80_877_103;
+const PG_GSSENC_REQUEST: i32 = 80_877_104;
+const PG_CANCEL_REQUEST: i32 = 80_877_102;
+const PG_PROTOCOL_3: i32 = 196_608;
+
+/// PostgreSQL frontend/backend protocol (v3) framing.
+///
+/// A request runs up to the next message the server answers: a simple
+/// `Query`, `Sync`, a password/SASL message, a copy terminator, or an untyped
+/// startup/SSL message. The response runs to `ReadyForQuery`, or to an
+/// authentication request that needs a client reply. SCRAM exchanges carry
+/// per-connection nonces and cannot be replayed; record against a server
+/// using trust, password or MD5 authentication.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgresProtocol;
+
+fn pg_error(message: impl Into<String>) -> CassetteError {
+    CassetteError::Protocol {
+        protocol: "postgres",
+        message: message.into(),
+    }
+}
+
+fn read_i32(buf: &[u8], at: usize) -> Option<i32> {
+    let bytes = buf.get(at..at + 4)?;
+    Some(i32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i16(buf: &[u8], at: usize) -> Option<i16> {
+    let bytes = buf.get(at..at + 2)?;
+    Some(i16::from_be_bytes(bytes.try_into().ok()?))
+}
+
+/// One framed message: tag (`0` for untyped startup messages), payload and end.
+struct PgMessage<'a> {
+    tag: u8,
+    payload: &'a [u8],
+    end: usize,
+}
+
+/// Frames the message at `at`, or `None` if it is incomplete.
+fn pg_message(buf: &[u8], at: usize) -> Result<Option<PgMessage<'_>>, CassetteError> {
+    let Some(&tag) = buf.get(at) else {
+        return Ok(None);
+    };
+    // Untyped messages start with their length, whose high byte is zero.
+    let (header, tag) = if tag == 0 { (0, 0) } else { (1, tag) };
+    let Some(len) = read_i32(buf, at + header) else {
+        return Ok(None);
+    };
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len >= 4 + 4 * usize::from(tag == 0))
+        .ok_or_else(|| pg_error(format!("invalid message length {len}")))?;
+    let end = at + header + len;
+    Ok((buf.len() >= end).then(|| PgMessage {
+        tag,
+        payload: &buf[at + header + 4..end],
+        end,
+    }))
+}
+
+fn pg_messages(buf: &[u8]) -> Result<Vec<PgMessage<'_>>, CassetteError> {
+    let mut messages = Vec::new();
+    let mut at = 0;
+    while let Some(message) = pg_message(buf, at)? {
+        at = message.end;
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+fn cstr(payload: &[u8], at: usize) -> Result<(String, usize), CassetteError> {
+    let rest = payload.get(at..).unwrap_or_default();
+    let len = rest
+        .iter()
+        .position(|byte| *byte == 0)
+        .ok_or_else(|| pg_error("unterminated string"))?;
+    Ok((display_bytes(&rest[..len]), at + len + 1))
+}
+
+fn push_bind(canonical: &mut CanonicalRequest, payload: &[u8]) -> Result<(), CassetteError> {
+    let truncated = || pg_error("truncated Bind");
+    let (portal, at) = cstr(payload, 0)?;
+    let (statement, mut at) = cstr(payload, at)?;
+    canonical.push("bind", statement);
+    if !portal.is_empty() {
+        canonical.push("bind.portal", portal);
+    }
+    let formats = read_i16(payload, at).ok_or_else(truncated)?;
+    at += 2 + 2 * usize::try_from(formats).map_err(|_| truncated())?;
+    let params = read_i16(payload, at).ok_or_else(truncated)?;
+    at += 2;
+    for _ in 0..params {
+        let len = read_i32(payload, at).ok_or_else(truncated)?;
+        at += 4;
+        let value = match usize::try_from(len) {
+            Ok(len) => {
+                let bytes = payload.get(at..at + len).ok_or_else(truncated)?;
+                at += len;
+                display_bytes(bytes)
+            }
+            Err(_) => "NULL".to_string(),
+        };
+        canonical.push("bind.param", value);
+    }
+    Ok(())
+}
+
+fn push_startup(canonical: &mut CanonicalRequest, payload: &[u8]) -> Result<(), CassetteError> {
+    let code = read_i32(payload, 0).ok_or_else(|| pg_error("truncated startup message"))?;
+    match code {
+        PG_SSL_REQUEST => canonical.push("ssl_request", ""),
+        PG_GSSENC_REQUEST => canonical.push("gssenc_request", ""),
+        // The cancel key differs per connection.
+        PG_CANCEL_REQUEST => canonical.push("cancel_request", ""),
+        PG_PROTOCOL_3 => {
+            let mut params = Vec::new();
+            let mut at = 4;
+            while payload.get(at).is_some_and(|byte| *byte != 0) {
+                let (name, next) = cstr(payload, at)?;
+                let (value, next) = cstr(payload, next)?;
+                params.push((name, value));
+                at = next;
+            }
+            params.sort();
+            for (name, value) in params {
+                canonical.push(format!("startup.{name}"), value);
+            }
+        }
+        other => canonical.push("startup.protocol", other.to_string()),
+    }
+    Ok(())
+}
+
+impl WireProtocol for PostgresProtocol {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn request_len(&self, buf: &[u8]) -> Result<Option<usize>, CassetteError> {
+        let mut at = 0;
+        while let Some(message) = pg_message(buf, at)? {
+            if matches!(message.tag, 0 | b'Q' | b'S' | b'X' | b'p' | b'F' | b'c' | b'f') {
+                return Ok(Some(message.end));
+            }
+            at = message.end;
+        }
+        Ok(None)
+    }
+
+    fn response_len(
+        &self,
+        request: &[u8],
+        buf: &[u8],
+        eof: bool,
+    ) -> Result<Option<usize>, CassetteError> {
+        let messages = pg_messages(request)?;
+        match messages.last() {
+            None => return Ok(Some(0)),
+            Some(last) if last.tag == b'X' => return Ok(Some(0)),
+            Some(last) if last.tag == 0 => match read_i32(last.payload, 0) {
+                Some(PG_SSL_REQUEST | PG_GSSENC_REQUEST) => {
+                    return Ok((!buf.is_empty()).then_some(1));
+                }
+                Some(PG_CANCEL_REQUEST) => return Ok(Some(0)),
+                _ => {}
+            },
+            Some(_) => {}
+        }
+        let mut at = 0;
+        while let Some(&tag) = buf.get(at) {
+            let Some(len) = read_i32(buf, at + 1) else {
+                break;
+            };
+            let len = usize::try_from(len).map_err(|_| pg_error("negative length"))?;
+            let end = at + 1 + len;
+            if buf.len() < end {
+                break;
+            }
+            // AuthenticationOk (0) and SASLFinal (12) are followed by more.
+            let auth_code = read_i32(buf, at + 5).unwrap_or(0);
+            let needs_reply = tag == b'R' && auth_code != 0 && auth_code != 12;
+            if tag == b'Z' || needs_reply {
+                return Ok(Some(end));
+            }
+            at = end;
+        }
+        Ok((eof && !buf.is_empty()).then_some(buf.len()))
+    }
+
+    fn split_response(&self, response: &[u8]) -> Vec<(String, usize)> {
+        if response.len() == 1 {
+            return vec![("ssl".to_string(), 1)];
+        }
+        let mut frames = Vec::new();
+        let mut at = 0;
+        while let (Some(&tag), Some(len)) = (response.get(at), read_i32(response, at + 1)) {
+            let end = usize::try_from(len)
+                .map_or(response.len(), |len| at + 1 + len)
+                .min(response.len());
+            frames.push((char::from(tag).to_string(), end));
+            at = end;
+        }
+        if at < response.len() {
+            frames.push(("partial".to_string(), response.len()));
+        }
+        frames
+    }
+
+    fn canonicalize(&self, request: &[u8]) -> Result<CanonicalRequest, CassetteError> {
+        let mut canonical = CanonicalRequest::new(self.name());
+        for message in pg_messages(request)? {
+            let payload = message.payload;
+            match message.tag {
+                0 => push_startup(&mut canonical, payload)?,
+                b'Q' => canonical.push("query", cstr(payload, 0)?.0),
+                b'P' => {
+                    let (statement, at) = cstr(payload, 0)?;
+                    canonical.push("parse", cstr(payload, at)?.0);
+                    if !statement.is_empty() {
+                        canonical.push("parse.statement", statement);
+                    }
+                }
+                b'B' => push_bind(&mut canonical, payload)?,
+                b'D' | b'C' => {
+                    let kind = payload.first().map_or('?', |kind| char::from(*kind));
+                    let name = cstr(payload, 1)?.0;
+                    let key = if message.tag == b'D' { "describe" } else { "close" };
+                    canonical.push(key, format!("{kind}:{name}"));
+                }
+                b'E' => {
+                    let (portal, at) = cstr(payload, 0)?;
+                    let max_rows = read_i32(payload, at).unwrap_or(0);
+                    canonical.push("execute", format!("{portal}:{max_rows}"));
+                }
+                b'S' => canonical.push("sync", ""),
+                b'H' => canonical.push("flush", ""),
+                b'X' => canonical.push("terminate", ""),
+                b'p' => canonical.push("password", display_bytes(payload)),
+                b'd' => canonical.push("copy_data", display_bytes(payload)),
+                b'c' => canonical.push("copy_done", ""),
+                b'f' => canonical.push("copy_fail", cstr(payload, 0)?.0),
+                tag => canonical.push(
+                    format!("message.{}", char::from(tag)),
+                    display_bytes(payload),
+                ),
+            }
+        }
+        Ok(canonical)
+    }
+}
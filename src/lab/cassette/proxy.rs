@@ -0,0 +1,193 @@
+//! Recording proxy for the native runtime.
+
+use super::CassetteError;
+use super::format::{CassetteWriter, Interaction, ResponseFrame};
+use super::protocol::WireProtocol;
+use super::scrub::Scrubber;
+use crate::cx::Cx;
+use crate::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::time::wall_now;
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+const READ_CHUNK: usize = 16 * 1024;
+
+/// Forwards a client connection to the real dependency and records every
+/// exchange into a cassette.
+///
+/// Bytes are forwarded unchanged and as they arrive, so the client sees the
+/// dependency's real behavior. One proxy may serve several connections
+/// concurrently; interactions are appended in completion order. Bytes the
+/// upstream sends beyond a complete response (for example asynchronous
+/// Postgres notices between queries) are recorded as the start of the next
+/// response, at offset zero.
+pub struct RecordingProxy<W: Write> {
+    protocol: Arc<dyn WireProtocol>,
+    scrubbers: Vec<Arc<dyn Scrubber>>,
+    writer: Mutex<CassetteWriter<W>>,
+    origin: Mutex<Option<Time>>,
+    time_getter: fn() -> Time,
+}
+
+impl<W: Write> fmt::Debug for RecordingProxy<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingProxy")
+            .field("protocol", &self.protocol.name())
+            .field("scrubbers", &self.scrubbers)
+            .field("recorded", &self.recorded())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> RecordingProxy<W> {
+    /// Creates a proxy recording `protocol` traffic into `writer`.
+    pub fn new(protocol: impl WireProtocol + 'static, writer: CassetteWriter<W>) -> Self {
+        Self {
+            protocol: Arc::new(protocol),
+            scrubbers: Vec::new(),
+            writer: Mutex::new(writer),
+            origin: Mutex::new(None),
+            time_getter: wall_now,
+        }
+    }
+
+    /// Adds a scrubber; scrubbers run in the order added.
+    #[must_use]
+    pub fn with_scrubber(mut self, scrubber: impl Scrubber + 'static) -> Self {
+        self.scrubbers.push(Arc::new(scrubber));
+        self
+    }
+
+    /// Sets the clock used to timestamp frames.
+    #[must_use]
+    pub fn with_time_getter(mut self, time_getter: fn() -> Time) -> Self {
+        self.time_getter = time_getter;
+        self
+    }
+
+    /// Number of interactions recorded so far.
+    #[must_use]
+    pub fn recorded(&self) -> usize {
+        self.writer.lock().written()
+    }
+
+    /// Returns the cassette writer's sink.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().into_inner()
+    }
+
+    /// Proxies one client connection to `upstream` until the client closes,
+    /// the upstream closes, or `cx` is cancelled between exchanges.
+    ///
+    /// Returns the number of interactions recorded from this connection.
+    pub async fn serve_connection<C, U>(
+        &self,
+        cx: &Cx,
+        mut client: C,
+        mut upstream: U,
+    ) -> Result<usize, CassetteError>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+        U: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut chunk = vec![0_u8; READ_CHUNK];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut early: Vec<u8> = Vec::new();
+        let mut recorded = 0;
+        loop {
+            cx.checkpoint()
+                .map_err(|_| io::Error::new(io::ErrorKind::Interrupted, "recording cancelled"))?;
+            let request_len = loop {
+                if let Some(len) = self.protocol.request_len(&pending)? {
+                    break len;
+                }
+                let read = client.read(&mut chunk).await?;
+                if read == 0 {
+                    return Ok(recorded);
+                }
+                pending.extend_from_slice(&chunk[..read]);
+            };
+            let request: Vec<u8> = pending.drain(..request_len).collect();
+            upstream.write_all(&request).await?;
+            upstream.flush().await?;
+            let sent = (self.time_getter)();
+
+            // Bytes that arrived ahead of this request were already forwarded.
+            let mut response = std::mem::take(&mut early);
+            let mut arrivals = vec![(response.len(), sent)];
+            let mut eof = false;
+            let response_len = loop {
+                if let Some(len) = self.protocol.response_len(&request, &response, eof)? {
+                    break len;
+                }
+                if eof {
+                    break response.len();
+                }
+                let read = upstream.read(&mut chunk).await?;
+                if read == 0 {
+                    eof = true;
+                    continue;
+                }
+                client.write_all(&chunk[..read]).await?;
+                response.extend_from_slice(&chunk[..read]);
+                arrivals.push((response.len(), (self.time_getter)()));
+            };
+            client.flush().await?;
+
+            early = response.split_off(response_len);
+            let interaction = self.interaction(&request, &response, &arrivals, sent)?;
+            self.writer.lock().append(&interaction)?;
+            recorded += 1;
+            if eof {
+                return Ok(recorded);
+            }
+        }
+    }
+
+    fn interaction(
+        &self,
+        request: &[u8],
+        response: &[u8],
+        arrivals: &[(usize, Time)],
+        sent: Time,
+    ) -> Result<Interaction, CassetteError> {
+        let mut canonical = self.protocol.canonicalize(request)?;
+        for scrubber in &self.scrubbers {
+            scrubber.scrub_request(&mut canonical);
+        }
+        let mut frames = Vec::new();
+        let mut start = 0;
+        let splits = if response.is_empty() {
+            Vec::new()
+        } else {
+            self.protocol.split_response(response)
+        };
+        for (label, end) in splits {
+            // A frame has arrived once the read containing its last byte has.
+            let arrived = arrivals
+                .iter()
+                .find(|(received, _)| *received >= end)
+                .map_or(sent, |(_, at)| *at);
+            let mut frame = ResponseFrame {
+                label,
+                offset_ns: arrived.duration_since(sent),
+                data: response[start..end].to_vec(),
+            };
+            for scrubber in &self.scrubbers {
+                scrubber.scrub_response(self.protocol.name(), &mut frame);
+            }
+            frames.push(frame);
+            start = end;
+        }
+        let origin = *self.origin.lock().get_or_insert(sent);
+        Ok(Interaction {
+            fingerprint: canonical.fingerprint(),
+            request: canonical,
+            started_ns: sent.duration_since(origin),
+            frames,
+        })
+    }
+}
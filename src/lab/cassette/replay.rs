@@ -0,0 +1,249 @@
+//! Cassette-backed replay server for the lab runtime.
+
+use super::CassetteError;
+use super::fingerprint::{CanonicalRequest, FieldDiff};
+use super::format::{Cassette, Interaction, ResponseFrame};
+use super::protocol::WireProtocol;
+use super::scrub::Scrubber;
+use crate::cx::Cx;
+use crate::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::time::sleep_until;
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+const READ_CHUNK: usize = 16 * 1024;
+
+/// A replayed request that matched no recorded interaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// The request as canonicalized (and scrubbed) at replay time.
+    pub request: CanonicalRequest,
+    /// Its fingerprint.
+    pub fingerprint: String,
+    /// Fingerprint of the recorded request with the fewest differing fields.
+    pub nearest: Option<String>,
+    /// Differences from the nearest recorded request.
+    pub diff: Vec<FieldDiff>,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no recorded {} interaction matches request {}",
+            self.request.protocol, self.fingerprint
+        )?;
+        let Some(nearest) = &self.nearest else {
+            return write!(f, "; the cassette holds no {} interactions", self.request.protocol);
+        };
+        write!(f, "\nnearest recorded request {nearest} differs:")?;
+        for diff in &self.diff {
+            for line in diff.to_string().lines() {
+                write!(f, "\n  {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recorded response chosen for a replayed request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayResponse {
+    /// Fingerprint of the matched interaction.
+    pub fingerprint: String,
+    /// Response frames with their recorded offsets.
+    pub frames: Vec<ResponseFrame>,
+}
+
+impl ReplayResponse {
+    /// Virtual time at which each frame is released for a request that
+    /// completed at `request_done`.
+    #[must_use]
+    pub fn schedule(&self, request_done: Time) -> Vec<(Time, &[u8])> {
+        self.frames
+            .iter()
+            .map(|frame| {
+                let due = request_done.saturating_add_nanos(frame.offset_ns);
+                (due, frame.data.as_slice())
+            })
+            .collect()
+    }
+
+    /// All response bytes.
+    #[must_use]
+    pub fn bytes(&self) -> Vec<u8> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.data.iter().copied())
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// Requests served so far per fingerprint.
+    cursors: BTreeMap<String, usize>,
+    /// Times each interaction was served.
+    served: Vec<u64>,
+    mismatches: Vec<ReplayMismatch>,
+}
+
+/// Serves recorded responses for one protocol under virtual time.
+///
+/// Identical requests are answered with their recorded responses in
+/// recording order; once those run out the last one is repeated. Every
+/// response frame is released at its recorded offset from the end of the
+/// request, measured on the runtime's timer (the lab's virtual clock), so
+/// replays are deterministic and independent of real latency.
+pub struct ReplayServer {
+    protocol: Arc<dyn WireProtocol>,
+    scrubbers: Vec<Arc<dyn Scrubber>>,
+    interactions: Vec<Interaction>,
+    by_fingerprint: BTreeMap<String, Vec<usize>>,
+    state: Mutex<ReplayState>,
+}
+
+impl fmt::Debug for ReplayServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplayServer")
+            .field("protocol", &self.protocol.name())
+            .field("interactions", &self.interactions.len())
+            .field("scrubbers", &self.scrubbers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReplayServer {
+    /// Creates a server for the `protocol` interactions in `cassette`.
+    pub fn new(cassette: Cassette, protocol: impl WireProtocol + 'static) -> Self {
+        let interactions: Vec<Interaction> = cassette
+            .interactions
+            .into_iter()
+            .filter(|interaction| interaction.request.protocol == protocol.name())
+            .collect();
+        let mut by_fingerprint: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (index, interaction) in interactions.iter().enumerate() {
+            by_fingerprint
+                .entry(interaction.fingerprint.clone())
+                .or_default()
+                .push(index);
+        }
+        let state = ReplayState {
+            served: vec![0; interactions.len()],
+            ..ReplayState::default()
+        };
+        Self {
+            protocol: Arc::new(protocol),
+            scrubbers: Vec::new(),
+            interactions,
+            by_fingerprint,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Adds a scrubber; use the same scrubbers the cassette was recorded with.
+    #[must_use]
+    pub fn with_scrubber(mut self, scrubber: impl Scrubber + 'static) -> Self {
+        self.scrubbers.push(Arc::new(scrubber));
+        self
+    }
+
+    /// Picks the recorded response for a complete `request`.
+    ///
+    /// Mismatches are returned as [`CassetteError::Mismatch`] and also kept
+    /// for [`mismatches`](Self::mismatches).
+    pub fn respond(&self, request: &[u8]) -> Result<ReplayResponse, CassetteError> {
+        let mut canonical = self.protocol.canonicalize(request)?;
+        for scrubber in &self.scrubbers {
+            scrubber.scrub_request(&mut canonical);
+        }
+        let fingerprint = canonical.fingerprint();
+        let mut state = self.state.lock();
+        if let Some(indices) = self.by_fingerprint.get(&fingerprint) {
+            let nth = state.cursors.entry(fingerprint.clone()).or_default();
+            let index = indices[(*nth).min(indices.len() - 1)];
+            *nth += 1;
+            state.served[index] += 1;
+            return Ok(ReplayResponse {
+                fingerprint,
+                frames: self.interactions[index].frames.clone(),
+            });
+        }
+        let nearest = self
+            .interactions
+            .iter()
+            .map(|interaction| (interaction, canonical.diff(&interaction.request)))
+            .min_by_key(|(_, diff)| diff.len());
+        let (nearest, diff) = nearest.map_or_else(
+            || (None, Vec::new()),
+            |(interaction, diff)| (Some(interaction.fingerprint.clone()), diff),
+        );
+        let mismatch = ReplayMismatch {
+            request: canonical,
+            fingerprint,
+            nearest,
+            diff,
+        };
+        state.mismatches.push(mismatch.clone());
+        Err(CassetteError::Mismatch(Box::new(mismatch)))
+    }
+
+    /// Requests that matched nothing so far.
+    #[must_use]
+    pub fn mismatches(&self) -> Vec<ReplayMismatch> {
+        self.state.lock().mismatches.clone()
+    }
+
+    /// Recorded interactions that have not been served.
+    #[must_use]
+    pub fn unused(&self) -> Vec<&Interaction> {
+        let state = self.state.lock();
+        self.interactions
+            .iter()
+            .zip(&state.served)
+            .filter(|(_, served)| **served == 0)
+            .map(|(interaction, _)| interaction)
+            .collect()
+    }
+
+    /// Serves one connection until the client closes it.
+    ///
+    /// Returns the number of requests answered. An unmatched request ends
+    /// the connection with [`CassetteError::Mismatch`], which carries the
+    /// diff against the nearest recorded request.
+    pub async fn serve_connection<S>(&self, cx: &Cx, mut stream: S) -> Result<usize, CassetteError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut chunk = vec![0_u8; READ_CHUNK];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut served = 0;
+        loop {
+            cx.checkpoint()
+                .map_err(|_| io::Error::new(io::ErrorKind::Interrupted, "replay cancelled"))?;
+            let request_len = loop {
+                if let Some(len) = self.protocol.request_len(&pending)? {
+                    break len;
+                }
+                let read = stream.read(&mut chunk).await?;
+                if read == 0 {
+                    return Ok(served);
+                }
+                pending.extend_from_slice(&chunk[..read]);
+            };
+            let request: Vec<u8> = pending.drain(..request_len).collect();
+            let request_done = cx.now();
+            let response = self.respond(&request)?;
+            for (due, data) in response.schedule(request_done) {
+                sleep_until(due).await;
+                stream.write_all(data).await?;
+            }
+            stream.flush().await?;
+            served += 1;
+        }
+    }
+}
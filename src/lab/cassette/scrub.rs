@@ -0,0 +1,99 @@
+//! Scrubbing of sensitive fields before they reach a cassette.
+
+use super::fingerprint::CanonicalRequest;
+use super::format::ResponseFrame;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Replacement value written by [`RedactFields`].
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Hook that removes secrets from recorded traffic.
+///
+/// Requests are scrubbed in canonical form before fingerprinting, both when
+/// recording and when replaying, so a scrubbed field (an `Authorization`
+/// header, a password message) still matches on replay. Responses are
+/// scrubbed frame by frame before they are written.
+pub trait Scrubber: fmt::Debug + Send + Sync {
+    /// Scrubs a canonical request.
+    fn scrub_request(&self, _request: &mut CanonicalRequest) {}
+
+    /// Scrubs one response frame of `protocol`.
+    fn scrub_response(&self, _protocol: &str, _frame: &mut ResponseFrame) {}
+}
+
+/// Replaces the value of the named canonical request fields with [`REDACTED`].
+///
+/// Keys are canonical field keys such as `header.authorization`,
+/// `query.api_key` or `password`.
+#[derive(Debug, Clone, Default)]
+pub struct RedactFields {
+    keys: BTreeSet<String>,
+}
+
+impl RedactFields {
+    /// Redacts every field named in `keys`.
+    #[must_use]
+    pub fn new<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        Self {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Scrubber for RedactFields {
+    fn scrub_request(&self, request: &mut CanonicalRequest) {
+        for (key, value) in &mut request.fields {
+            if self.keys.contains(key) {
+                REDACTED.clone_into(value);
+            }
+        }
+    }
+}
+
+/// Masks every occurrence of the given strings in response frames with `*`.
+///
+/// The mask has the same length as the secret, so content lengths and
+/// protocol framing stay valid.
+#[derive(Debug, Clone, Default)]
+pub struct MaskResponseText {
+    secrets: Vec<Vec<u8>>,
+}
+
+impl MaskResponseText {
+    /// Masks every non-empty string in `secrets`.
+    #[must_use]
+    pub fn new<I, S>(secrets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            secrets: secrets
+                .into_iter()
+                .map(|secret| secret.as_ref().as_bytes().to_vec())
+                .filter(|secret| !secret.is_empty())
+                .collect(),
+        }
+    }
+}
+
+impl Scrubber for MaskResponseText {
+    fn scrub_response(&self, _protocol: &str, frame: &mut ResponseFrame) {
+        for secret in &self.secrets {
+            let mut at = 0;
+            while let Some(found) = frame.data[at..]
+                .windows(secret.len())
+                .position(|window| window == secret.as_slice())
+            {
+                let start = at + found;
+                frame.data[start..start + secret.len()].fill(b'*');
+                at = start + secret.len();
+            }
+        }
+    }
+}
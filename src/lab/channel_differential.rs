@@ -0,0 +1,1504 @@
+//! Differential harness comparing channel behavior across the lab and native
+//! runtimes.
+//!
+//! A [`ChannelProgram`] is a small concurrent script: a channel kind, a
+//! capacity, and one op list per sender and receiver actor. The harness runs
+//! the same program under the lab runtime for several seeds and under the
+//! native multi-threaded runtime for several repetitions (with seeded chaos
+//! perturbation), then reduces every execution to a [`CanonicalOutcome`]:
+//!
+//! - the multiset of values delivered to each receiver,
+//! - the error kinds observed by every actor, and
+//! - a final-state summary (commits, per-sender order violations, receivers
+//!   that saw the channel close, actors that never finished).
+//!
+//! Each canonical outcome is checked against the sequential specification
+//! computed by [`specify`]. Ops whose result legitimately depends on the
+//! interleaving (racing sends and receives that are polled once, oneshot
+//! receives on a cancelled context, watch updates) feed their observed
+//! result into the spec, but only when that result is admissible for the
+//! channel. Any other difference, in either direction, is reported as a
+//! [`DifferentialFailure`] carrying the program, the runtime, and the seed or
+//! repetition needed to reproduce it.
+//!
+//! Channels are opened through a [`ChannelFactory`], so a test can wrap the
+//! real endpoints in a deliberately broken implementation and confirm the
+//! harness notices.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::lab::channel_differential::{
+//!     ChannelProgram, ChannelSuite, DifferentialConfig, run_differential,
+//! };
+//!
+//! let program = ChannelProgram::generate(7, 8);
+//! let report = run_differential(&ChannelSuite, &program, &DifferentialConfig::default(), 7)
+//!     .unwrap_or_else(|failure| panic!("{failure}"));
+//! assert_eq!(report.executions, 6);
+//! ```
+
+use crate::channel::{broadcast, mpsc, oneshot, watch};
+use crate::cx::Cx;
+use crate::lab::dual_run::{RuntimeKind, derive_scenario_seed};
+use crate::lab::runtime::LabRuntime;
+use crate::runtime::chaos::NativeChaosConfig;
+use crate::runtime::task_handle::JoinError;
+use crate::runtime::{RuntimeBuilder, yield_now};
+use crate::types::{Budget, RegionId, TaskId};
+use crate::util::{ArenaIndex, DetRng};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::Duration;
+
+/// Maximum number of sender or receiver actors in a generated program.
+const MAX_ACTORS: usize = 3;
+
+/// Maximum number of script bytes consumed by [`ChannelProgram::from_bytes`].
+const MAX_FUZZ_OPS: usize = 64;
+
+/// Maximum ops per actor; op indices are packed into the low 16 bits of a value.
+const MAX_OPS_PER_ACTOR: usize = 256;
+
+// ─── Programs ───────────────────────────────────────────────────────────────
+
+/// Channel flavor exercised by a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ChannelKind {
+    /// Bounded multi-producer, single-consumer queue.
+    Mpsc,
+    /// Single-value channel.
+    Oneshot,
+    /// Fan-out channel; every receiver sees every message.
+    Broadcast,
+    /// Latest-value cell.
+    Watch,
+}
+
+impl ChannelKind {
+    /// Every channel kind, in a stable order.
+    pub const ALL: [Self; 4] = [Self::Mpsc, Self::Oneshot, Self::Broadcast, Self::Watch];
+
+    /// Returns the kind as a stable string.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Mpsc => "mpsc",
+            Self::Oneshot => "oneshot",
+            Self::Broadcast => "broadcast",
+            Self::Watch => "watch",
+        }
+    }
+}
+
+impl fmt::Display for ChannelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One step of a sender actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SenderOp {
+    /// Send a value, waiting for capacity.
+    Send,
+    /// Reserve a permit, then commit a value through it.
+    ReserveCommit,
+    /// Reserve a permit, then abort it without sending.
+    ReserveAbort,
+    /// Send on an already-cancelled context.
+    CancelledSend,
+    /// Poll a send once and drop it if it is not immediately ready.
+    RacingSend,
+    /// Yield to the scheduler.
+    Yield,
+    /// Drop this sender; later ops are skipped.
+    Close,
+}
+
+impl SenderOp {
+    /// Every sender op, in the order used to decode fuzz input.
+    pub const ALL: [Self; 7] = [
+        Self::Send,
+        Self::ReserveCommit,
+        Self::ReserveAbort,
+        Self::CancelledSend,
+        Self::RacingSend,
+        Self::Yield,
+        Self::Close,
+    ];
+
+    /// Returns the op as a stable string.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Send => "send",
+            Self::ReserveCommit => "reserve_commit",
+            Self::ReserveAbort => "reserve_abort",
+            Self::CancelledSend => "cancelled_send",
+            Self::RacingSend => "racing_send",
+            Self::Yield => "yield",
+            Self::Close => "close",
+        }
+    }
+
+    /// Returns true if the op uses up a oneshot sender.
+    const fn consumes_oneshot(self) -> bool {
+        !matches!(self, Self::Yield)
+    }
+}
+
+/// One step of a receiver actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReceiverOp {
+    /// Receive, waiting for a value or closure.
+    Recv,
+    /// Poll a receive once and drop it if it is not immediately ready.
+    RacingRecv,
+    /// Receive on an already-cancelled context.
+    CancelledRecv,
+    /// Yield to the scheduler.
+    Yield,
+}
+
+impl ReceiverOp {
+    /// Every receiver op, in the order used to decode fuzz input.
+    pub const ALL: [Self; 4] = [Self::Recv, Self::RacingRecv, Self::CancelledRecv, Self::Yield];
+
+    /// Returns the op as a stable string.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Recv => "recv",
+            Self::RacingRecv => "racing_recv",
+            Self::CancelledRecv => "cancelled_recv",
+            Self::Yield => "yield",
+        }
+    }
+}
+
+/// A concurrent channel script.
+///
+/// Programs are normalized on construction so every script is meaningful for
+/// its channel kind: oneshot and watch have a single sender, mpsc and oneshot
+/// have a single receiver, broadcast capacity covers every send (so lag is
+/// never expected), and watch senders never see a cancelled context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelProgram {
+    /// Channel flavor.
+    pub kind: ChannelKind,
+    /// Channel capacity (ignored by oneshot and watch).
+    pub capacity: usize,
+    /// One op list per sender actor.
+    pub senders: Vec<Vec<SenderOp>>,
+    /// One op list per receiver actor.
+    pub receivers: Vec<Vec<ReceiverOp>>,
+}
+
+impl ChannelProgram {
+    /// Builds a normalized program from explicit scripts.
+    #[must_use]
+    pub fn new(
+        kind: ChannelKind,
+        capacity: usize,
+        senders: Vec<Vec<SenderOp>>,
+        receivers: Vec<Vec<ReceiverOp>>,
+    ) -> Self {
+        Self {
+            kind,
+            capacity,
+            senders,
+            receivers,
+        }
+        .normalized()
+    }
+
+    /// Generates a program from a seed with at most `max_ops` ops per actor.
+    #[must_use]
+    pub fn generate(seed: u64, max_ops: usize) -> Self {
+        let mut rng = DetRng::new(seed);
+        let kind = ChannelKind::ALL[rng.next_usize(ChannelKind::ALL.len())];
+        let capacity = 1 + rng.next_usize(4);
+        let sender_count = 1 + rng.next_usize(MAX_ACTORS);
+        let receiver_count = 1 + rng.next_usize(MAX_ACTORS);
+
+        let mut senders = Vec::with_capacity(sender_count);
+        for _ in 0..sender_count {
+            let len = rng.next_usize(max_ops + 1);
+            senders.push((0..len).map(|_| random_sender_op(&mut rng)).collect());
+        }
+        let mut receivers = Vec::with_capacity(receiver_count);
+        for _ in 0..receiver_count {
+            let len = rng.next_usize(max_ops + 1);
+            receivers.push((0..len).map(|_| random_receiver_op(&mut rng)).collect());
+        }
+        Self::new(kind, capacity, senders, receivers)
+    }
+
+    /// Decodes a program from fuzz input.
+    ///
+    /// The first byte selects the kind, actor counts, and capacity; each
+    /// following byte appends one op to one actor. Returns `None` for empty
+    /// input.
+    #[must_use]
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let (&header, script) = data.split_first()?;
+        let kind = ChannelKind::ALL[usize::from(header & 0b11)];
+        let sender_count = 1 + usize::from((header >> 2) & 0b11) % MAX_ACTORS;
+        let receiver_count = 1 + usize::from((header >> 4) & 0b11) % MAX_ACTORS;
+        let capacity = 1 + usize::from(header >> 6);
+
+        let mut senders = vec![Vec::new(); sender_count];
+        let mut receivers = vec![Vec::new(); receiver_count];
+        for &byte in script.iter().take(MAX_FUZZ_OPS) {
+            let actor = usize::from(byte >> 4);
+            let op = usize::from(byte & 0b111);
+            if byte & 0b1000 == 0 {
+                senders[actor % sender_count].push(SenderOp::ALL[op % SenderOp::ALL.len()]);
+            } else {
+                receivers[actor % receiver_count].push(ReceiverOp::ALL[op % ReceiverOp::ALL.len()]);
+            }
+        }
+        Some(Self::new(kind, capacity, senders, receivers))
+    }
+
+    /// Returns the value sent by op `op` of sender `sender`.
+    ///
+    /// Values are unique per op and encode their origin, so receivers can
+    /// check per-sender ordering. Zero is reserved for the watch initial value.
+    #[must_use]
+    pub const fn value(sender: usize, op: usize) -> u32 {
+        (((sender + 1) as u32) << 16) | (op as u32 & 0xFFFF)
+    }
+
+    /// Returns the total number of sender ops.
+    #[must_use]
+    pub fn sender_ops(&self) -> usize {
+        self.senders.iter().map(Vec::len).sum()
+    }
+
+    fn normalized(mut self) -> Self {
+        if self.senders.is_empty() {
+            self.senders.push(Vec::new());
+        }
+        if self.receivers.is_empty() {
+            self.receivers.push(Vec::new());
+        }
+        for ops in &mut self.senders {
+            ops.truncate(MAX_OPS_PER_ACTOR);
+        }
+        for ops in &mut self.receivers {
+            ops.truncate(MAX_OPS_PER_ACTOR);
+        }
+        self.senders.truncate(MAX_ACTORS);
+        self.receivers.truncate(MAX_ACTORS);
+        self.capacity = self.capacity.max(1);
+
+        match self.kind {
+            ChannelKind::Mpsc => self.receivers.truncate(1),
+            ChannelKind::Oneshot => {
+                self.senders.truncate(1);
+                self.receivers.truncate(1);
+            }
+            ChannelKind::Broadcast => self.capacity = self.capacity.max(self.sender_ops()),
+            ChannelKind::Watch => {
+                self.senders.truncate(1);
+                for op in &mut self.senders[0] {
+                    if *op == SenderOp::CancelledSend {
+                        *op = SenderOp::Yield;
+                    }
+                }
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for ChannelProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "kind={} capacity={}", self.kind, self.capacity)?;
+        for (index, ops) in self.senders.iter().enumerate() {
+            write!(f, "\n  sender {index}:")?;
+            for op in ops {
+                write!(f, " {}", op.as_str())?;
+            }
+        }
+        for (index, ops) in self.receivers.iter().enumerate() {
+            write!(f, "\n  receiver {index}:")?;
+            for op in ops {
+                write!(f, " {}", op.as_str())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn random_sender_op(rng: &mut DetRng) -> SenderOp {
+    match rng.next_usize(16) {
+        0 => SenderOp::Close,
+        1 => SenderOp::CancelledSend,
+        2 | 3 => SenderOp::ReserveAbort,
+        4 | 5 => SenderOp::RacingSend,
+        6 | 7 => SenderOp::Yield,
+        8..=10 => SenderOp::ReserveCommit,
+        _ => SenderOp::Send,
+    }
+}
+
+fn random_receiver_op(rng: &mut DetRng) -> ReceiverOp {
+    match rng.next_usize(8) {
+        0 => ReceiverOp::CancelledRecv,
+        1 | 2 => ReceiverOp::RacingRecv,
+        3 => ReceiverOp::Yield,
+        _ => ReceiverOp::Recv,
+    }
+}
+
+// ─── Channel endpoints ──────────────────────────────────────────────────────
+
+/// Result of a single actor op.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpResult {
+    /// A value was committed to the channel.
+    Committed,
+    /// A reserved permit was released without sending.
+    Aborted,
+    /// A value was received.
+    Value(u32),
+    /// A racing op was not ready on its only poll.
+    Pending,
+    /// The op observed cancellation.
+    Cancelled,
+    /// The op observed a closed or disconnected channel.
+    Closed,
+    /// A broadcast receiver fell behind by this many messages.
+    Lagged(u64),
+    /// The channel returned an error no op should produce.
+    Unexpected,
+    /// The actor yielded.
+    Yielded,
+    /// The sender was dropped by a `Close` op.
+    Dropped,
+    /// The sender was already gone, so the op did nothing.
+    Skipped,
+}
+
+impl OpResult {
+    /// Returns the error kind this result represents, if any.
+    #[must_use]
+    pub const fn error_kind(self) -> Option<ErrorKind> {
+        match self {
+            Self::Cancelled => Some(ErrorKind::Cancelled),
+            Self::Closed => Some(ErrorKind::Closed),
+            Self::Lagged(_) => Some(ErrorKind::Lagged),
+            Self::Unexpected => Some(ErrorKind::Unexpected),
+            _ => None,
+        }
+    }
+}
+
+/// Boxed future returned by endpoint ops.
+pub type OpFuture<'a> = Pin<Box<dyn Future<Output = OpResult> + Send + 'a>>;
+
+/// Sending half of a channel under test.
+pub trait DifferentialSender: Send {
+    /// Sends `value`.
+    fn send<'a>(&'a mut self, cx: &'a Cx, value: u32) -> OpFuture<'a>;
+
+    /// Reserves a permit, then commits `commit` through it or aborts it when
+    /// `commit` is `None`.
+    fn reserve<'a>(&'a mut self, cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a>;
+}
+
+/// Receiving half of a channel under test.
+pub trait DifferentialReceiver: Send {
+    /// Receives one value.
+    fn recv<'a>(&'a mut self, cx: &'a Cx) -> OpFuture<'a>;
+
+    /// Returns the current value of a latest-value channel.
+    fn latest(&mut self) -> Option<u32> {
+        None
+    }
+}
+
+/// Endpoints opened for one execution, one per program actor.
+pub struct ChannelEndpoints {
+    /// Senders, indexed like [`ChannelProgram::senders`].
+    pub senders: Vec<Box<dyn DifferentialSender>>,
+    /// Receivers, indexed like [`ChannelProgram::receivers`].
+    pub receivers: Vec<Box<dyn DifferentialReceiver>>,
+}
+
+impl fmt::Debug for ChannelEndpoints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelEndpoints")
+            .field("senders", &self.senders.len())
+            .field("receivers", &self.receivers.len())
+            .finish()
+    }
+}
+
+/// Opens a fresh channel for each execution of a program.
+pub trait ChannelFactory: Send + Sync {
+    /// Opens endpoints matching the program's kind, capacity, and actors.
+    fn open(&self, program: &ChannelProgram) -> ChannelEndpoints;
+}
+
+/// Factory for the crate's own channel suite.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelSuite;
+
+impl ChannelFactory for ChannelSuite {
+    fn open(&self, program: &ChannelProgram) -> ChannelEndpoints {
+        let sender_count = program.senders.len();
+        let receiver_count = program.receivers.len();
+        match program.kind {
+            ChannelKind::Mpsc => {
+                let (tx, rx) = mpsc::channel(program.capacity);
+                ChannelEndpoints {
+                    senders: (0..sender_count)
+                        .map(|_| Box::new(MpscSender(tx.clone())) as Box<dyn DifferentialSender>)
+                        .collect(),
+                    receivers: vec![Box::new(MpscReceiver(rx))],
+                }
+            }
+            ChannelKind::Oneshot => {
+                let (tx, rx) = oneshot::channel();
+                ChannelEndpoints {
+                    senders: vec![Box::new(OneshotSender(Some(tx)))],
+                    receivers: vec![Box::new(OneshotReceiver(rx))],
+                }
+            }
+            ChannelKind::Broadcast => {
+                let (tx, rx) = broadcast::channel(program.capacity);
+                let mut receivers: Vec<Box<dyn DifferentialReceiver>> =
+                    vec![Box::new(BroadcastReceiver(rx))];
+                for _ in 1..receiver_count {
+                    receivers.push(Box::new(BroadcastReceiver(tx.subscribe())));
+                }
+                ChannelEndpoints {
+                    senders: (0..sender_count)
+                        .map(|_| {
+                            Box::new(BroadcastSender(tx.clone())) as Box<dyn DifferentialSender>
+                        })
+                        .collect(),
+                    receivers,
+                }
+            }
+            ChannelKind::Watch => {
+                let (tx, rx) = watch::channel(0);
+                let mut receivers: Vec<Box<dyn DifferentialReceiver>> =
+                    vec![Box::new(WatchReceiver(rx))];
+                for _ in 1..receiver_count {
+                    receivers.push(Box::new(WatchReceiver(tx.subscribe())));
+                }
+                ChannelEndpoints {
+                    senders: vec![Box::new(WatchSender(tx))],
+                    receivers,
+                }
+            }
+        }
+    }
+}
+
+const fn mpsc_send_result<T>(result: &Result<(), mpsc::SendError<T>>) -> OpResult {
+    match result {
+        Ok(()) => OpResult::Committed,
+        Err(mpsc::SendError::Disconnected(_)) => OpResult::Closed,
+        Err(mpsc::SendError::Cancelled(_)) => OpResult::Cancelled,
+        Err(mpsc::SendError::Full(_)) => OpResult::Unexpected,
+    }
+}
+
+const fn oneshot_send_result<T>(result: &Result<(), oneshot::SendError<T>>) -> OpResult {
+    match result {
+        Ok(()) => OpResult::Committed,
+        Err(oneshot::SendError::Disconnected(_)) => OpResult::Closed,
+        Err(oneshot::SendError::Cancelled(_)) => OpResult::Cancelled,
+    }
+}
+
+struct MpscSender(mpsc::Sender<u32>);
+
+impl DifferentialSender for MpscSender {
+    fn send<'a>(&'a mut self, cx: &'a Cx, value: u32) -> OpFuture<'a> {
+        Box::pin(async move { mpsc_send_result(&self.0.send(cx, value).await) })
+    }
+
+    fn reserve<'a>(&'a mut self, cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.reserve(cx).await {
+                Ok(permit) => match commit {
+                    Some(value) => mpsc_send_result(&permit.try_send(value)),
+                    None => {
+                        permit.abort();
+                        OpResult::Aborted
+                    }
+                },
+                Err(err) => mpsc_send_result(&Err(err)),
+            }
+        })
+    }
+}
+
+struct MpscReceiver(mpsc::Receiver<u32>);
+
+impl DifferentialReceiver for MpscReceiver {
+    fn recv<'a>(&'a mut self, cx: &'a Cx) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.recv(cx).await {
+                Ok(value) => OpResult::Value(value),
+                Err(mpsc::RecvError::Disconnected) => OpResult::Closed,
+                Err(mpsc::RecvError::Cancelled) => OpResult::Cancelled,
+                Err(mpsc::RecvError::Empty) => OpResult::Unexpected,
+            }
+        })
+    }
+}
+
+struct OneshotSender(Option<oneshot::Sender<u32>>);
+
+impl DifferentialSender for OneshotSender {
+    fn send<'a>(&'a mut self, cx: &'a Cx, value: u32) -> OpFuture<'a> {
+        Box::pin(async move {
+            let Some(tx) = self.0.take() else {
+                return OpResult::Skipped;
+            };
+            oneshot_send_result(&tx.send(cx, value))
+        })
+    }
+
+    fn reserve<'a>(&'a mut self, cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a> {
+        Box::pin(async move {
+            let Some(tx) = self.0.take() else {
+                return OpResult::Skipped;
+            };
+            match tx.reserve(cx) {
+                Ok(permit) => match commit {
+                    Some(value) => oneshot_send_result(&permit.send(value)),
+                    None => {
+                        permit.abort();
+                        OpResult::Aborted
+                    }
+                },
+                Err(err) => oneshot_send_result(&Err(err)),
+            }
+        })
+    }
+}
+
+struct OneshotReceiver(oneshot::Receiver<u32>);
+
+impl DifferentialReceiver for OneshotReceiver {
+    fn recv<'a>(&'a mut self, cx: &'a Cx) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.recv(cx).await {
+                Ok(value) => OpResult::Value(value),
+                Err(oneshot::RecvError::Closed) => OpResult::Closed,
+                Err(oneshot::RecvError::Cancelled) => OpResult::Cancelled,
+                Err(oneshot::RecvError::PolledAfterCompletion) => OpResult::Unexpected,
+            }
+        })
+    }
+}
+
+struct BroadcastSender(broadcast::Sender<u32>);
+
+impl BroadcastSender {
+    const fn reserve_error(err: &broadcast::SendError<()>) -> OpResult {
+        match err {
+            broadcast::SendError::Closed(()) => OpResult::Closed,
+            broadcast::SendError::Cancelled(()) => OpResult::Cancelled,
+        }
+    }
+}
+
+impl DifferentialSender for BroadcastSender {
+    fn send<'a>(&'a mut self, cx: &'a Cx, value: u32) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.send(cx, value) {
+                Ok(_) => OpResult::Committed,
+                Err(broadcast::SendError::Closed(_)) => OpResult::Closed,
+                Err(broadcast::SendError::Cancelled(_)) => OpResult::Cancelled,
+            }
+        })
+    }
+
+    fn reserve<'a>(&'a mut self, cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.reserve(cx) {
+                Ok(permit) => match commit {
+                    Some(value) => {
+                        permit.send(value);
+                        OpResult::Committed
+                    }
+                    None => {
+                        drop(permit);
+                        OpResult::Aborted
+                    }
+                },
+                Err(err) => Self::reserve_error(&err),
+            }
+        })
+    }
+}
+
+struct BroadcastReceiver(broadcast::Receiver<u32>);
+
+impl DifferentialReceiver for BroadcastReceiver {
+    fn recv<'a>(&'a mut self, cx: &'a Cx) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.recv(cx).await {
+                Ok(value) => OpResult::Value(value),
+                Err(broadcast::RecvError::Lagged(missed)) => OpResult::Lagged(missed),
+                Err(broadcast::RecvError::Closed) => OpResult::Closed,
+                Err(broadcast::RecvError::Cancelled) => OpResult::Cancelled,
+                Err(broadcast::RecvError::PolledAfterCompletion) => OpResult::Unexpected,
+            }
+        })
+    }
+}
+
+/// Watch sends never wait and take no context; `CancelledSend` is normalized
+/// away for this kind.
+struct WatchSender(watch::Sender<u32>);
+
+impl DifferentialSender for WatchSender {
+    fn send<'a>(&'a mut self, _cx: &'a Cx, value: u32) -> OpFuture<'a> {
+        let result = match self.0.send(value) {
+            Ok(()) => OpResult::Committed,
+            Err(watch::SendError::Closed(_)) => OpResult::Closed,
+        };
+        Box::pin(std::future::ready(result))
+    }
+
+    fn reserve<'a>(&'a mut self, _cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a> {
+        let result = match commit {
+            Some(value) => match self.0.send_modify(|current| *current = value) {
+                Ok(()) => OpResult::Committed,
+                Err(_) => OpResult::Closed,
+            },
+            None => OpResult::Aborted,
+        };
+        Box::pin(std::future::ready(result))
+    }
+}
+
+struct WatchReceiver(watch::Receiver<u32>);
+
+impl DifferentialReceiver for WatchReceiver {
+    fn recv<'a>(&'a mut self, cx: &'a Cx) -> OpFuture<'a> {
+        Box::pin(async move {
+            match self.0.changed(cx).await {
+                Ok(()) => OpResult::Value(self.0.borrow_and_update_clone()),
+                Err(watch::RecvError::Closed) => OpResult::Closed,
+                Err(watch::RecvError::Cancelled) => OpResult::Cancelled,
+                Err(watch::RecvError::PolledAfterCompletion) => OpResult::Unexpected,
+            }
+        })
+    }
+
+    fn latest(&mut self) -> Option<u32> {
+        Some(self.0.borrow_and_update_clone())
+    }
+}
+
+// ─── Actors ─────────────────────────────────────────────────────────────────
+
+/// What one receiver observed during an execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReceiverTrace {
+    /// Results of the scripted ops, in op order.
+    pub scripted: Vec<OpResult>,
+    /// Results of the receives issued after the script until the channel
+    /// reported a non-value result.
+    pub drained: Vec<OpResult>,
+    /// Final value of a latest-value channel.
+    pub latest: Option<u32>,
+}
+
+/// Raw per-actor results of one execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawRun {
+    /// Results per sender, in op order. Empty for stalled actors.
+    pub senders: Vec<Vec<OpResult>>,
+    /// Trace per receiver. Default for stalled actors.
+    pub receivers: Vec<ReceiverTrace>,
+    /// Actors that had not finished when the execution ended.
+    pub stalled: u32,
+}
+
+/// Returns a detached context that is already cancelled.
+fn cancelled_cx() -> Cx {
+    let cx = Cx::new(
+        RegionId::from_arena(ArenaIndex::new(1, 1)),
+        TaskId::from_arena(ArenaIndex::new(1, 1)),
+        Budget::INFINITE,
+    );
+    cx.set_cancel_requested(true);
+    cx
+}
+
+/// Polls `future` exactly once.
+async fn poll_once<F: Future + Unpin>(mut future: F) -> Option<F::Output> {
+    std::future::poll_fn(|context| match Pin::new(&mut future).poll(context) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => Poll::Ready(None),
+    })
+    .await
+}
+
+async fn drive_sender(
+    sender: Box<dyn DifferentialSender>,
+    index: usize,
+    ops: Vec<SenderOp>,
+) -> Vec<OpResult> {
+    let cx = Cx::current().expect("differential actors run inside a runtime task");
+    let cancelled = cancelled_cx();
+    let mut sender = Some(sender);
+    let mut results = Vec::with_capacity(ops.len());
+    for (op_index, op) in ops.into_iter().enumerate() {
+        let Some(tx) = sender.as_mut() else {
+            results.push(OpResult::Skipped);
+            continue;
+        };
+        let value = ChannelProgram::value(index, op_index);
+        let result = match op {
+            SenderOp::Send => tx.send(&cx, value).await,
+            SenderOp::ReserveCommit => tx.reserve(&cx, Some(value)).await,
+            SenderOp::ReserveAbort => tx.reserve(&cx, None).await,
+            SenderOp::CancelledSend => tx.send(&cancelled, value).await,
+            SenderOp::RacingSend => poll_once(tx.send(&cx, value))
+                .await
+                .unwrap_or(OpResult::Pending),
+            SenderOp::Yield => {
+                yield_now().await;
+                OpResult::Yielded
+            }
+            SenderOp::Close => {
+                sender = None;
+                OpResult::Dropped
+            }
+        };
+        results.push(result);
+    }
+    results
+}
+
+async fn drive_receiver(
+    mut receiver: Box<dyn DifferentialReceiver>,
+    ops: Vec<ReceiverOp>,
+    drain_limit: usize,
+) -> ReceiverTrace {
+    let cx = Cx::current().expect("differential actors run inside a runtime task");
+    let cancelled = cancelled_cx();
+    let mut trace = ReceiverTrace::default();
+    for op in ops {
+        let result = match op {
+            ReceiverOp::Recv => receiver.recv(&cx).await,
+            ReceiverOp::RacingRecv => poll_once(receiver.recv(&cx))
+                .await
+                .unwrap_or(OpResult::Pending),
+            ReceiverOp::CancelledRecv => receiver.recv(&cancelled).await,
+            ReceiverOp::Yield => {
+                yield_now().await;
+                OpResult::Yielded
+            }
+        };
+        trace.scripted.push(result);
+    }
+    for _ in 0..=drain_limit {
+        let result = receiver.recv(&cx).await;
+        trace.drained.push(result);
+        if !matches!(result, OpResult::Value(_)) {
+            break;
+        }
+    }
+    trace.latest = receiver.latest();
+    trace
+}
+
+// ─── Canonical outcomes ─────────────────────────────────────────────────────
+
+/// Error kind observed by an actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ErrorKind {
+    /// The op was cancelled.
+    Cancelled,
+    /// The channel was closed or disconnected.
+    Closed,
+    /// A broadcast receiver lagged.
+    Lagged,
+    /// An error the op should never produce.
+    Unexpected,
+}
+
+/// Final-state summary of an execution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FinalState {
+    /// Values committed by senders.
+    pub committed: u32,
+    /// Receives that arrived out of order relative to their sender.
+    pub order_violations: u32,
+    /// Receivers whose drain ended with the channel closed.
+    pub terminated_receivers: u32,
+    /// Actors that never finished.
+    pub stalled: u32,
+}
+
+/// Interleaving-independent summary of an execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CanonicalOutcome {
+    /// Sorted values delivered to each receiver. Watch receivers record only
+    /// their final value.
+    pub delivered: Vec<Vec<u32>>,
+    /// Error kinds observed across all actors.
+    pub errors: BTreeMap<ErrorKind, u32>,
+    /// Final-state summary.
+    pub final_state: FinalState,
+}
+
+fn bump(errors: &mut BTreeMap<ErrorKind, u32>, kind: ErrorKind) {
+    *errors.entry(kind).or_insert(0) += 1;
+}
+
+/// Counts values that do not strictly follow an earlier value from the same
+/// sender.
+fn order_violations(values: &[u32]) -> u32 {
+    let mut last = BTreeMap::new();
+    let mut violations = 0;
+    for &value in values {
+        let op = value & 0xFFFF;
+        if let Some(previous) = last.insert(value >> 16, op)
+            && previous >= op
+        {
+            violations += 1;
+        }
+    }
+    violations
+}
+
+/// Reduces a raw execution to its canonical outcome.
+///
+/// The result does not depend on how sender ops interleaved, only on what
+/// each receiver got, which errors surfaced, and how the execution ended.
+#[must_use]
+pub fn canonicalize(program: &ChannelProgram, run: &RawRun) -> CanonicalOutcome {
+    let mut outcome = CanonicalOutcome::default();
+    for result in run.senders.iter().flatten() {
+        if *result == OpResult::Committed {
+            outcome.final_state.committed += 1;
+        } else if let Some(kind) = result.error_kind() {
+            bump(&mut outcome.errors, kind);
+        }
+    }
+    for trace in &run.receivers {
+        let mut values = Vec::new();
+        for result in trace.scripted.iter().chain(&trace.drained) {
+            match *result {
+                OpResult::Value(value) => values.push(value),
+                other => {
+                    if let Some(kind) = other.error_kind() {
+                        bump(&mut outcome.errors, kind);
+                    }
+                }
+            }
+        }
+        outcome.final_state.order_violations += order_violations(&values);
+        if trace.drained.last() == Some(&OpResult::Closed) {
+            outcome.final_state.terminated_receivers += 1;
+        }
+        if program.kind == ChannelKind::Watch {
+            values = vec![trace.latest.unwrap_or(0)];
+        } else {
+            values.sort_unstable();
+        }
+        outcome.delivered.push(values);
+    }
+    outcome.final_state.stalled = run.stalled;
+    outcome
+}
+
+/// Computes the canonical outcome the sequential channel model allows.
+///
+/// Deterministic ops are derived from the program alone. Ops whose result
+/// depends on the interleaving read their observed result from `run`, which
+/// is counted only when the model admits it; anything else leaves the spec
+/// unchanged, so [`canonicalize`] disagrees and the mismatch surfaces.
+#[must_use]
+pub fn specify(program: &ChannelProgram, run: &RawRun) -> CanonicalOutcome {
+    let mut errors = BTreeMap::new();
+    let mut committed = Vec::new();
+    for (index, ops) in program.senders.iter().enumerate() {
+        let observed = run.senders.get(index).map_or(&[][..], Vec::as_slice);
+        for (op_index, &op) in ops.iter().enumerate() {
+            let result = observed.get(op_index).copied();
+            match op {
+                SenderOp::Send | SenderOp::ReserveCommit => {
+                    committed.push(ChannelProgram::value(index, op_index));
+                }
+                SenderOp::RacingSend if result == Some(OpResult::Committed) => {
+                    committed.push(ChannelProgram::value(index, op_index));
+                }
+                SenderOp::CancelledSend => bump(&mut errors, ErrorKind::Cancelled),
+                _ => {}
+            }
+            let consumed = program.kind == ChannelKind::Oneshot && op.consumes_oneshot();
+            if op == SenderOp::Close || consumed {
+                break;
+            }
+        }
+    }
+
+    let latest = committed.last().copied().unwrap_or(0);
+    let mut delivered = Vec::with_capacity(program.receivers.len());
+    for (index, ops) in program.receivers.iter().enumerate() {
+        let observed = run
+            .receivers
+            .get(index)
+            .map_or(&[][..], |trace| trace.scripted.as_slice());
+        let mut remaining = committed.len();
+        for (op_index, &op) in ops.iter().enumerate() {
+            let result = observed.get(op_index).copied();
+            if program.kind == ChannelKind::Watch {
+                match (op, result) {
+                    (ReceiverOp::CancelledRecv, _) => bump(&mut errors, ErrorKind::Cancelled),
+                    (ReceiverOp::Recv | ReceiverOp::RacingRecv, Some(OpResult::Closed)) => {
+                        bump(&mut errors, ErrorKind::Closed);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match (op, result) {
+                (ReceiverOp::Recv, _) => {
+                    if remaining > 0 {
+                        remaining -= 1;
+                    } else {
+                        bump(&mut errors, ErrorKind::Closed);
+                    }
+                }
+                (ReceiverOp::CancelledRecv, _) if program.kind != ChannelKind::Oneshot => {
+                    bump(&mut errors, ErrorKind::Cancelled);
+                }
+                (ReceiverOp::RacingRecv | ReceiverOp::CancelledRecv, Some(OpResult::Value(_)))
+                    if remaining > 0 =>
+                {
+                    remaining -= 1;
+                }
+                (ReceiverOp::RacingRecv | ReceiverOp::CancelledRecv, Some(OpResult::Closed))
+                    if remaining == 0 =>
+                {
+                    bump(&mut errors, ErrorKind::Closed);
+                }
+                (ReceiverOp::CancelledRecv, Some(OpResult::Cancelled)) => {
+                    bump(&mut errors, ErrorKind::Cancelled);
+                }
+                _ => {}
+            }
+        }
+        // The drain ends with exactly one closed receive.
+        bump(&mut errors, ErrorKind::Closed);
+        if program.kind == ChannelKind::Watch {
+            delivered.push(vec![latest]);
+        } else {
+            let mut values = committed.clone();
+            values.sort_unstable();
+            delivered.push(values);
+        }
+    }
+
+    CanonicalOutcome {
+        delivered,
+        errors,
+        final_state: FinalState {
+            committed: committed.len() as u32,
+            order_violations: 0,
+            terminated_receivers: program.receivers.len() as u32,
+            stalled: 0,
+        },
+    }
+}
+
+// ─── Executions ─────────────────────────────────────────────────────────────
+
+/// Which runtime ran an execution and how to reproduce it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Execution {
+    /// Lab runtime with the given seed.
+    Lab {
+        /// Lab scheduler seed.
+        seed: u64,
+    },
+    /// Native multi-threaded runtime.
+    Native {
+        /// Repetition index within the sweep.
+        repetition: u32,
+        /// Chaos seed, when chaos perturbation was enabled.
+        chaos_seed: Option<u64>,
+    },
+}
+
+impl Execution {
+    /// Returns the runtime that ran this execution.
+    #[must_use]
+    pub const fn runtime_kind(&self) -> RuntimeKind {
+        match self {
+            Self::Lab { .. } => RuntimeKind::Lab,
+            Self::Native { .. } => RuntimeKind::Live,
+        }
+    }
+}
+
+impl fmt::Display for Execution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lab { seed } => write!(f, "lab seed=0x{seed:X}"),
+            Self::Native {
+                repetition,
+                chaos_seed: Some(chaos_seed),
+            } => write!(f, "native repetition={repetition} chaos_seed=0x{chaos_seed:X}"),
+            Self::Native {
+                repetition,
+                chaos_seed: None,
+            } => write!(f, "native repetition={repetition}"),
+        }
+    }
+}
+
+/// How many executions each program gets.
+#[derive(Debug, Clone)]
+pub struct DifferentialConfig {
+    /// Lab executions, each with its own derived seed.
+    pub lab_seeds: u32,
+    /// Native executions.
+    pub native_repetitions: u32,
+    /// Worker threads for the native runtime.
+    pub native_workers: usize,
+    /// Install seeded chaos perturbation on the native runtime. Has no
+    /// effect unless the `chaos` feature is enabled.
+    pub chaos: bool,
+    /// Wall-clock limit for one native execution; actors still running when
+    /// it expires are reported as stalled.
+    pub native_timeout: Duration,
+}
+
+impl Default for DifferentialConfig {
+    /// Bounded profile suitable for every CI run.
+    fn default() -> Self {
+        Self {
+            lab_seeds: 4,
+            native_repetitions: 2,
+            native_workers: 2,
+            chaos: true,
+            native_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl DifferentialConfig {
+    /// Long-running profile for nightly sweeps.
+    #[must_use]
+    pub fn nightly() -> Self {
+        Self {
+            lab_seeds: 32,
+            native_repetitions: 16,
+            native_workers: 4,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of executions per program.
+    #[must_use]
+    pub const fn executions(&self) -> u32 {
+        self.lab_seeds + self.native_repetitions
+    }
+}
+
+/// Summary of a passing differential run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferentialReport {
+    /// Executions that matched the spec.
+    pub executions: u32,
+}
+
+/// An execution whose canonical outcome differed from the spec.
+#[derive(Debug, Clone)]
+pub struct DifferentialFailure {
+    /// The program that diverged.
+    pub program: ChannelProgram,
+    /// Base seed the executions were derived from.
+    pub seed: u64,
+    /// The diverging execution.
+    pub execution: Execution,
+    /// Outcome the spec allows.
+    pub expected: CanonicalOutcome,
+    /// Outcome the execution produced.
+    pub observed: CanonicalOutcome,
+    /// Raw per-actor results of the execution.
+    pub run: RawRun,
+}
+
+impl fmt::Display for DifferentialFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "channel differential mismatch on {} (base seed 0x{:X})",
+            self.execution, self.seed
+        )?;
+        writeln!(f, "program: {}", self.program)?;
+        writeln!(f, "expected: {:?}", self.expected)?;
+        writeln!(f, "observed: {:?}", self.observed)?;
+        write!(f, "run: {:?}", self.run)
+    }
+}
+
+impl std::error::Error for DifferentialFailure {}
+
+/// Runs `program` under every execution in `config` and checks each against
+/// the spec.
+///
+/// Lab seeds and chaos seeds are derived from `seed`, so the same arguments
+/// reproduce the same sweep.
+pub fn run_differential(
+    factory: &dyn ChannelFactory,
+    program: &ChannelProgram,
+    config: &DifferentialConfig,
+    seed: u64,
+) -> Result<DifferentialReport, Box<DifferentialFailure>> {
+    let mut executions = Vec::with_capacity(config.executions() as usize);
+    for index in 0..config.lab_seeds {
+        let lab_seed = derive_scenario_seed(seed, &format!("channel-differential/lab/{index}"));
+        executions.push(Execution::Lab { seed: lab_seed });
+    }
+    for repetition in 0..config.native_repetitions {
+        let chaos_seed = config.chaos.then(|| {
+            derive_scenario_seed(seed, &format!("channel-differential/native/{repetition}"))
+        });
+        executions.push(Execution::Native {
+            repetition,
+            chaos_seed,
+        });
+    }
+
+    for &execution in &executions {
+        let run = match execution {
+            Execution::Lab { seed } => run_lab(factory, program, seed),
+            Execution::Native { chaos_seed, .. } => {
+                run_native(factory, program, chaos_seed, config)
+            }
+        };
+        let expected = specify(program, &run);
+        let observed = canonicalize(program, &run);
+        if expected != observed {
+            return Err(Box::new(DifferentialFailure {
+                program: program.clone(),
+                seed,
+                execution,
+                expected,
+                observed,
+                run,
+            }));
+        }
+    }
+    Ok(DifferentialReport {
+        executions: executions.len() as u32,
+    })
+}
+
+fn joined<T: Default>(result: Result<Option<T>, JoinError>, stalled: &mut u32) -> T {
+    match result {
+        Ok(Some(value)) => value,
+        Ok(None) | Err(_) => {
+            *stalled += 1;
+            T::default()
+        }
+    }
+}
+
+/// Runs one execution on the lab runtime.
+#[must_use]
+pub fn run_lab(factory: &dyn ChannelFactory, program: &ChannelProgram, seed: u64) -> RawRun {
+    let mut runtime = LabRuntime::with_seed(seed);
+    let region = runtime.state.create_root_region(Budget::INFINITE);
+    let endpoints = factory.open(program);
+    let drain_limit = program.sender_ops();
+
+    let mut sender_handles = Vec::with_capacity(endpoints.senders.len());
+    for (index, (sender, ops)) in endpoints
+        .senders
+        .into_iter()
+        .zip(program.senders.iter().cloned())
+        .enumerate()
+    {
+        let (task, handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, drive_sender(sender, index, ops))
+            .expect("create lab sender task");
+        runtime.scheduler.lock().schedule(task, 0);
+        sender_handles.push(handle);
+    }
+    let mut receiver_handles = Vec::with_capacity(endpoints.receivers.len());
+    for (receiver, ops) in endpoints
+        .receivers
+        .into_iter()
+        .zip(program.receivers.iter().cloned())
+    {
+        let (task, handle) = runtime
+            .state
+            .create_task(
+                region,
+                Budget::INFINITE,
+                drive_receiver(receiver, ops, drain_limit),
+            )
+            .expect("create lab receiver task");
+        runtime.scheduler.lock().schedule(task, 0);
+        receiver_handles.push(handle);
+    }
+
+    runtime.run_until_quiescent();
+
+    let mut run = RawRun::default();
+    for handle in &mut sender_handles {
+        run.senders.push(joined(handle.try_join(), &mut run.stalled));
+    }
+    for handle in &mut receiver_handles {
+        run.receivers.push(joined(handle.try_join(), &mut run.stalled));
+    }
+    run
+}
+
+/// Runs one execution on the native multi-threaded runtime.
+#[must_use]
+pub fn run_native(
+    factory: &dyn ChannelFactory,
+    program: &ChannelProgram,
+    chaos_seed: Option<u64>,
+    config: &DifferentialConfig,
+) -> RawRun {
+    let mut builder = RuntimeBuilder::new().worker_threads(config.native_workers);
+    if let Some(seed) = chaos_seed {
+        builder = builder.chaos(NativeChaosConfig::new(seed));
+    }
+    let runtime = builder.build().expect("build native differential runtime");
+    let handle = runtime.handle();
+    let endpoints = factory.open(program);
+    let drain_limit = program.sender_ops();
+    let actors = (endpoints.senders.len() + endpoints.receivers.len()) as u32;
+
+    let senders: Vec<_> = endpoints
+        .senders
+        .into_iter()
+        .zip(program.senders.iter().cloned())
+        .enumerate()
+        .map(|(index, (sender, ops))| handle.spawn(drive_sender(sender, index, ops)))
+        .collect();
+    let receivers: Vec<_> = endpoints
+        .receivers
+        .into_iter()
+        .zip(program.receivers.iter().cloned())
+        .map(|(receiver, ops)| handle.spawn(drive_receiver(receiver, ops, drain_limit)))
+        .collect();
+
+    let timeout = config.native_timeout;
+    let collected = runtime.block_on(handle.spawn(async move {
+        let collect = async move {
+            let mut run = RawRun::default();
+            for join in senders {
+                run.senders.push(join.await);
+            }
+            for join in receivers {
+                run.receivers.push(join.await);
+            }
+            run
+        };
+        crate::time::timeout(crate::time::wall_now(), timeout, collect)
+            .await
+            .ok()
+    }));
+    collected.unwrap_or_else(|| RawRun {
+        stalled: actors,
+        ..RawRun::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::test_utils::init_test_logging;
+
+    fn init_test(name: &str) {
+        init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Test-only wrapper that reports its first send as committed without
+    /// sending anything.
+    struct LossyFactory;
+
+    struct LossySender {
+        inner: Box<dyn DifferentialSender>,
+        dropped: bool,
+    }
+
+    impl DifferentialSender for LossySender {
+        fn send<'a>(&'a mut self, cx: &'a Cx, value: u32) -> OpFuture<'a> {
+            if self.dropped {
+                return self.inner.send(cx, value);
+            }
+            self.dropped = true;
+            Box::pin(std::future::ready(OpResult::Committed))
+        }
+
+        fn reserve<'a>(&'a mut self, cx: &'a Cx, commit: Option<u32>) -> OpFuture<'a> {
+            self.inner.reserve(cx, commit)
+        }
+    }
+
+    impl ChannelFactory for LossyFactory {
+        fn open(&self, program: &ChannelProgram) -> ChannelEndpoints {
+            let mut endpoints = ChannelSuite.open(program);
+            endpoints.senders = endpoints
+                .senders
+                .into_iter()
+                .map(|inner| {
+                    Box::new(LossySender {
+                        inner,
+                        dropped: false,
+                    }) as Box<dyn DifferentialSender>
+                })
+                .collect();
+            endpoints
+        }
+    }
+
+    fn two_sender_mpsc() -> ChannelProgram {
+        ChannelProgram::new(
+            ChannelKind::Mpsc,
+            2,
+            vec![
+                vec![SenderOp::Send, SenderOp::Yield, SenderOp::Send],
+                vec![SenderOp::ReserveCommit, SenderOp::CancelledSend],
+            ],
+            vec![vec![ReceiverOp::Recv, ReceiverOp::Yield]],
+        )
+    }
+
+    #[test]
+    fn harness_detects_spec_violation_in_wrapped_channel() {
+        init_test("harness_detects_spec_violation_in_wrapped_channel");
+        let program = ChannelProgram::new(
+            ChannelKind::Mpsc,
+            4,
+            vec![vec![SenderOp::Send, SenderOp::Send, SenderOp::Send]],
+            vec![vec![ReceiverOp::Recv]],
+        );
+
+        let honest = run_differential(&ChannelSuite, &program, &DifferentialConfig::default(), 3);
+        crate::assert_with_log!(honest.is_ok(), "real mpsc passes", true, honest.is_ok());
+
+        let failure = run_differential(&LossyFactory, &program, &DifferentialConfig::default(), 3)
+            .expect_err("lossy sender must diverge from the spec");
+        crate::assert_with_log!(
+            failure.execution.runtime_kind() == RuntimeKind::Lab,
+            "first execution reports the divergence",
+            RuntimeKind::Lab,
+            failure.execution.runtime_kind()
+        );
+        let expected = failure.expected.delivered[0].len();
+        let observed = failure.observed.delivered[0].len();
+        crate::assert_with_log!(
+            expected == 3 && observed == 2,
+            "dropped message is missing",
+            (3, 2),
+            (expected, observed)
+        );
+        let dump = failure.to_string();
+        let reproducible = dump.contains("sender 0: send send send") && dump.contains("lab seed=");
+        crate::assert_with_log!(
+            reproducible,
+            "dump carries program and seed",
+            true,
+            reproducible
+        );
+        crate::test_complete!("harness_detects_spec_violation_in_wrapped_channel");
+    }
+
+    #[test]
+    fn canonicalization_ignores_interleaving_but_not_reordering() {
+        init_test("canonicalization_ignores_interleaving_but_not_reordering");
+        let program = two_sender_mpsc();
+        let a0 = ChannelProgram::value(0, 0);
+        let a2 = ChannelProgram::value(0, 2);
+        let b0 = ChannelProgram::value(1, 0);
+        let senders = vec![
+            vec![OpResult::Committed, OpResult::Yielded, OpResult::Committed],
+            vec![OpResult::Committed, OpResult::Cancelled],
+        ];
+        let run_with = |arrivals: [u32; 3]| RawRun {
+            senders: senders.clone(),
+            receivers: vec![ReceiverTrace {
+                scripted: vec![OpResult::Value(arrivals[0]), OpResult::Yielded],
+                drained: vec![
+                    OpResult::Value(arrivals[1]),
+                    OpResult::Value(arrivals[2]),
+                    OpResult::Closed,
+                ],
+                latest: None,
+            }],
+            stalled: 0,
+        };
+
+        let first = canonicalize(&program, &run_with([a0, b0, a2]));
+        let second = canonicalize(&program, &run_with([b0, a0, a2]));
+        crate::assert_with_log!(first == second, "interleavings agree", &first, &second);
+        let spec = specify(&program, &run_with([a0, b0, a2]));
+        crate::assert_with_log!(first == spec, "matches spec", &spec, &first);
+
+        let reordered = canonicalize(&program, &run_with([a2, b0, a0]));
+        let violations = reordered.final_state.order_violations;
+        crate::assert_with_log!(violations == 1, "reorder is visible", 1, violations);
+
+        let lab_a = run_lab(&ChannelSuite, &program, 11);
+        let lab_b = run_lab(&ChannelSuite, &program, 11);
+        crate::assert_with_log!(lab_a == lab_b, "same lab seed replays", &lab_a, &lab_b);
+        crate::test_complete!("canonicalization_ignores_interleaving_but_not_reordering");
+    }
+
+    #[test]
+    fn default_config_sweeps_every_kind_within_bounds() {
+        init_test("default_config_sweeps_every_kind_within_bounds");
+        let config = DifferentialConfig::default();
+        let bounded = config.executions() <= 8 && config.native_timeout <= Duration::from_secs(10);
+        crate::assert_with_log!(bounded, "default profile is bounded", true, bounded);
+
+        let mut kinds = std::collections::BTreeSet::new();
+        let mut seed = 0;
+        while kinds.len() < ChannelKind::ALL.len() {
+            let program = ChannelProgram::generate(seed, 6);
+            if kinds.insert(program.kind) {
+                let report = run_differential(&ChannelSuite, &program, &config, seed)
+                    .unwrap_or_else(|failure| panic!("{failure}"));
+                crate::assert_with_log!(
+                    report.executions == config.executions(),
+                    "every execution ran",
+                    config.executions(),
+                    report.executions
+                );
+            }
+            seed += 1;
+        }
+        crate::test_complete!("default_config_sweeps_every_kind_within_bounds");
+    }
+}
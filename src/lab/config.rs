@@ -114,8 +114,11 @@
 
 use crate::lab::chaos::ChaosConfig;
 use crate::lab::oracle::{ORACLE_ALL, OracleRegistry, OracleRegistryError};
+use crate::lab::runtime::LabResource;
+use crate::runtime::scheduler::RegionFairness;
 use crate::trace::RecorderConfig;
 use crate::util::DetRng;
+use std::time::Duration;
 
 /// Configuration for the lab runtime.
 #[derive(Debug, Clone)]
@@ -176,6 +179,64 @@ pub struct LabConfig {
     /// Empty means [`ORACLE_ALL`], preserving the historical "check every
     /// suite-reported oracle" default.
     pub oracle_selection: Vec<String>,
+    /// Hard resource limits checked against the run's resource certificate.
+    ///
+    /// Unlike [`max_steps`](Self::max_steps), these limits never stop
+    /// execution; exceeding one is reported as an invariant violation.
+    pub resource_limits: LabResourceLimits,
+    /// How far a `timeout()` may overshoot the ambient deadline before its
+    /// clamp is logged as a warning.
+    pub deadline_clamp_slack: Duration,
+    /// How ready-lane dispatches are shared.
+    ///
+    /// [`RegionFairness::RegionFair`] shares them between regions by weighted
+    /// virtual runtime so a region with many ready tasks cannot starve one
+    /// with few; see [`LabRuntime::set_region_weight`](crate::lab::LabRuntime::set_region_weight).
+    pub region_fairness: RegionFairness,
+}
+
+/// Hard resource limits for a lab run.
+///
+/// Each `None` field is unchecked. Exceeding a limit does not abort the run:
+/// the violation (with the step at which the limit was first exceeded) is
+/// surfaced in the [`LabRunReport`](crate::lab::LabRunReport) alongside
+/// oracle failures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LabResourceLimits {
+    /// Maximum scheduler steps.
+    pub max_steps: Option<u64>,
+    /// Maximum concurrently live tasks.
+    pub max_live_tasks: Option<u64>,
+    /// Maximum concurrently pending obligations.
+    pub max_pending_obligations: Option<u64>,
+    /// Maximum tracked buffer memory, in bytes.
+    pub max_tracked_memory_bytes: Option<u64>,
+    /// Maximum total virtual time, in nanoseconds.
+    pub max_virtual_time_nanos: Option<u64>,
+}
+
+impl LabResourceLimits {
+    /// Returns the configured limit for `resource`, if any.
+    #[must_use]
+    pub const fn limit_for(&self, resource: LabResource) -> Option<u64> {
+        match resource {
+            LabResource::Steps => self.max_steps,
+            LabResource::LiveTasks => self.max_live_tasks,
+            LabResource::PendingObligations => self.max_pending_obligations,
+            LabResource::TrackedMemoryBytes => self.max_tracked_memory_bytes,
+            LabResource::VirtualTimeNanos => self.max_virtual_time_nanos,
+        }
+    }
+
+    /// Returns true when no limit is configured.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.max_steps.is_none()
+            && self.max_live_tasks.is_none()
+            && self.max_pending_obligations.is_none()
+            && self.max_tracked_memory_bytes.is_none()
+            && self.max_virtual_time_nanos.is_none()
+    }
 }
 
 impl LabConfig {
@@ -197,6 +258,15 @@ impl LabConfig {
             enable_cancellation_oracle: true,
             panic_on_cancellation_violation: true,
             oracle_selection: Vec::new(),
+            resource_limits: LabResourceLimits {
+                max_steps: None,
+                max_live_tasks: None,
+                max_pending_obligations: None,
+                max_tracked_memory_bytes: None,
+                max_virtual_time_nanos: None,
+            },
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+            region_fairness: RegionFairness::TaskFair,
         }
     }
 
@@ -373,6 +443,21 @@ impl LabConfig {
         self
     }
 
+    /// Sets how far a `timeout()` may overshoot the ambient deadline before
+    /// its clamp is logged as a warning.
+    #[must_use]
+    pub const fn with_deadline_clamp_slack(mut self, slack: Duration) -> Self {
+        self.deadline_clamp_slack = slack;
+        self
+    }
+
+    /// Sets how ready-lane dispatches are shared between regions.
+    #[must_use]
+    pub const fn with_region_fairness(mut self, fairness: RegionFairness) -> Self {
+        self.region_fairness = fairness;
+        self
+    }
+
     /// Returns true if replay recording is enabled.
     #[must_use]
     pub fn has_replay_recording(&self) -> bool {
@@ -403,6 +488,13 @@ impl LabConfig {
         self
     }
 
+    /// Sets hard resource limits checked against the run's resource certificate.
+    #[must_use]
+    pub const fn with_resource_limits(mut self, limits: LabResourceLimits) -> Self {
+        self.resource_limits = limits;
+        self
+    }
+
     /// Returns true if real-time cancellation protocol oracle verification is enabled.
     #[must_use]
     pub const fn has_cancellation_oracle(&self) -> bool {
@@ -6096,6 +6096,7 @@ mod tests {
             steps_delta: 100,
             steps_total: 100,
             quiescent: true,
+            blocked_by_pauses: false,
             now_nanos: 0,
             trace_len: 10,
             trace_fingerprint: 0xABCD,
@@ -6113,6 +6114,9 @@ mod tests {
             refinement_firewall_event_seq: None,
             refinement_counterexample_prefix_len: None,
             refinement_firewall_skipped_due_to_trace_truncation: false,
+            resource_usage: crate::lab::runtime::LabResourceUsage::default(),
+            resource_limit_violations: vec![],
+            region_vruntimes: vec![],
         }
     }
 
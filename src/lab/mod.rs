@@ -10,6 +10,12 @@
 //! - Await point tracking for cancellation injection
 //! - Integrated cancellation injection with oracle verification
 //! - Chaos testing with configurable failure injection
+//! - Simulated disk I/O with latency, fault injection, and crash durability
+//!   ([`SimFs`], Unix only)
+//! - Lab-vs-native differential checks for the channel suite
+//!   ([`channel_differential`])
+//! - Record/replay of external HTTP and Postgres dependencies under virtual
+//!   time ([`cassette`])
 //!
 //! # Quick Start
 //!
@@ -55,6 +61,8 @@ pub mod atp_lab {
 pub mod atp_path;
 #[cfg(feature = "benchmark-adapters")]
 pub mod benchmark_cartel;
+pub mod cassette;
+pub mod channel_differential;
 pub mod chaos;
 pub mod config;
 pub mod conformal;
@@ -83,6 +91,8 @@ pub mod replay_minimization;
 pub mod runtime;
 pub mod scenario;
 pub mod scenario_runner;
+#[cfg(unix)]
+pub mod sim_fs;
 pub mod snapshot_restore;
 pub mod spork_harness;
 pub mod swarm_replay;
@@ -101,7 +111,7 @@ pub use atp_lab::{
     AtpLabFault, AtpLabOracleConfig, AtpLabRegime, AtpLabReplayMetadata, AtpLabScenario,
     AtpLabTransferSpec, AtpTransferLabPlan,
 };
-pub use config::LabConfig;
+pub use config::{LabConfig, LabResourceLimits};
 pub use conformal::{
     CalibrationReport, ConformalCalibrator, ConformalConfig, ConformityScore, CoverageTracker,
     PredictionSet,
@@ -168,8 +178,11 @@ pub use meta::{
     MetaResult, MetaRunner, builtin_mutations, invariant_from_violation,
 };
 pub use network::{
-    DeterministicNetwork, Fault as NetworkFault, JitterModel, LatencyModel, NetworkConditions,
-    NetworkConfig, NetworkMetrics, NetworkTraceEvent, NetworkTraceKind, Packet,
+    ConnectFaults, ConnectSim, ConnectSimConfig, ConnectTraceEvent, ConnectTraceKind,
+    DeterministicNetwork, Fault as NetworkFault, HandshakeFault, HandshakeOutcome, JitterModel,
+    LatencyModel, NetworkConditions, NetworkConfig, NetworkMetrics, NetworkTraceEvent,
+    NetworkTraceKind, Packet, ResolveFault, ResolveOutcome, SimMulticastDomain, SimResolver,
+    SimTls, SimUpstream,
 };
 pub use numa::{
     NumaCachePressureInput, NumaCachePressureProjection, NumaPressureClass,
@@ -206,19 +219,25 @@ pub use replay::{
 };
 pub use runtime::{
     AutoAdvanceTermination, HarnessAttachmentKind, HarnessAttachmentRef, LabAutoCrashpack,
-    LabAutoCrashpackError, LabConfigSummary, LabRunReport, LabRuntime, LabTraceCertificateSummary,
+    LabAutoCrashpackError, LabConfigSummary, LabResource, LabResourceLimitViolation,
+    LabResourceUsage, LabRunReport, LabRuntime, LabTraceCertificateSummary, PauseTarget,
     SporkHarnessReport, VirtualTimeReport, run_async_lab_test_with_config, run_async_under_lab,
     run_async_under_lab_with_config,
 };
 pub use scenario::{
     CancellationSection, CancellationStrategy, ChaosSection, FaultAction, FaultEvent, IncludeRef,
-    LabSection, LatencySpec, LinkConditions, NetworkPreset, NetworkSection, Participant,
-    SCENARIO_SCHEMA_VERSION, Scenario, ValidationError as ScenarioValidationError,
+    LabSection, LatencySpec, LimitsSection, LinkConditions, NetworkPreset, NetworkSection,
+    Participant, SCENARIO_SCHEMA_VERSION, Scenario, ValidationError as ScenarioValidationError,
 };
 pub use scenario_runner::{
     ExplorationRunSummary, FilteredOracleReport, ScenarioExplorationResult, ScenarioRunResult,
     ScenarioRunner, ScenarioRunnerError as FrankenLabRunnerError, TraceCertificateSnapshot,
 };
+#[cfg(unix)]
+pub use sim_fs::{
+    SimFs, SimFsConfig, SimFsDecision, SimFsFailure, SimFsFault, SimFsFile, SimFsOp, SimFsRule,
+    SimFsTraceEvent,
+};
 pub use snapshot_restore::{
     RestorableSnapshot, RestoreError, SnapshotRestore, SnapshotStats, ValidationResult,
 };
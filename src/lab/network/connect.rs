@@ -0,0 +1,855 @@
+//! Deterministic DNS and TLS handshake failures for client resilience tests.
+//!
+//! [`ConnectSim`] stands in for the two connection-establishment phases that
+//! happen before a request reaches a server:
+//!
+//! - [`SimResolver`] answers lookups from a static zone, or from a per-name
+//!   script of [`ResolveFault`]s (NXDOMAIN, SERVFAIL, timeout, slow answer).
+//! - [`SimTls`] completes handshakes, or follows a per-endpoint script of
+//!   [`HandshakeFault`]s (slow success, certificate-verification failure,
+//!   handshake timeout, protocol-version mismatch).
+//!
+//! Scripts are consumed one entry per lookup or handshake; once a script runs
+//! out the phase succeeds with the configured base latency plus seeded
+//! jitter. Every latency advances the simulator's virtual clock, which also
+//! drives the [`EndpointSet`] of each [`SimUpstream`].
+//!
+//! Failures surface as the client's [`ClientError`], so their
+//! [`RequestPhase`](crate::http::h1::RequestPhase) and [`RetryClass`] select
+//! the resilience mechanism exactly as they do for a live client:
+//! [`ConnectSim::establish`] re-resolves after a resolve failure (keeping the
+//! stale endpoints) and records a handshake failure against the endpoint,
+//! moving to another one and eventually ejecting it.
+//!
+//! Every outcome is appended to the trace. [`ConnectFaults::from_trace`]
+//! turns a trace back into a script that replays it exactly, given the same
+//! zone and upstream configuration.
+
+use crate::http::h1::{ClientError, Method, RetryClass};
+use crate::net::dns::{LookupIp, Resolver};
+use crate::net::endpoint_set::{DnsEndpointSource, EndpointLease, EndpointSet, EndpointSetConfig};
+use crate::types::Time;
+use crate::util::DetRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const NANOS_PER_MS: u64 = 1_000_000;
+
+/// Scripted outcome of one lookup of a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResolveFault {
+    /// The name does not exist.
+    #[serde(rename = "nxdomain")]
+    NxDomain {
+        /// Virtual time until the answer arrives.
+        #[serde(default)]
+        latency_ms: u64,
+    },
+    /// The server failed to produce an answer.
+    #[serde(rename = "servfail")]
+    ServFail {
+        /// Virtual time until the answer arrives.
+        #[serde(default)]
+        latency_ms: u64,
+    },
+    /// No answer arrives; the lookup fails at the resolve timeout.
+    Timeout,
+    /// The zone's answer, after `latency_ms`. An answer slower than the
+    /// resolve timeout is a timeout.
+    Slow {
+        /// Virtual time until the answer arrives.
+        latency_ms: u64,
+    },
+}
+
+/// Scripted outcome of one TLS handshake with an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HandshakeFault {
+    /// The handshake completes after `latency_ms`. A handshake slower than
+    /// the handshake timeout is a timeout.
+    Success {
+        /// Virtual time until the handshake completes.
+        latency_ms: u64,
+    },
+    /// The server's certificate fails verification.
+    CertVerification {
+        /// Virtual time until the certificate is rejected.
+        #[serde(default)]
+        latency_ms: u64,
+    },
+    /// The server never answers; the handshake fails at the timeout.
+    Timeout,
+    /// Client and server share no protocol version.
+    VersionMismatch {
+        /// Virtual time until the server's alert arrives.
+        #[serde(default)]
+        latency_ms: u64,
+    },
+}
+
+/// Connection-establishment fault scripts: the `faults.connect` section of a
+/// scenario, held in
+/// [`Scenario::connect_faults`](crate::lab::scenario::Scenario::connect_faults).
+///
+/// ```yaml
+/// faults:
+///   connect:
+///     resolve:
+///       api.internal:
+///         - { kind: servfail, latency_ms: 5 }
+///         - { kind: slow, latency_ms: 800 }
+///     handshake:
+///       "10.0.0.1:443":
+///         - { kind: cert_verification }
+///         - { kind: timeout }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectFaults {
+    /// Per-name lookup scripts, consumed one entry per lookup.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resolve: BTreeMap<String, Vec<ResolveFault>>,
+    /// Per-endpoint handshake scripts, consumed one entry per handshake.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub handshake: BTreeMap<SocketAddr, Vec<HandshakeFault>>,
+}
+
+impl ConnectFaults {
+    /// Returns true if no script has any entry.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.resolve.values().all(Vec::is_empty) && self.handshake.values().all(Vec::is_empty)
+    }
+
+    /// Builds the script that reproduces `trace`: every recorded lookup and
+    /// handshake becomes one scripted entry with its recorded latency.
+    #[must_use]
+    pub fn from_trace(trace: &[ConnectTraceEvent]) -> Self {
+        let mut faults = Self::default();
+        for event in trace {
+            let latency_ms = event.latency_ms;
+            match &event.kind {
+                ConnectTraceKind::Resolve { name, outcome } => {
+                    let fault = match outcome {
+                        ResolveOutcome::Answer(_) => ResolveFault::Slow { latency_ms },
+                        ResolveOutcome::NxDomain => ResolveFault::NxDomain { latency_ms },
+                        ResolveOutcome::ServFail => ResolveFault::ServFail { latency_ms },
+                        ResolveOutcome::Timeout => ResolveFault::Timeout,
+                    };
+                    faults.resolve.entry(name.clone()).or_default().push(fault);
+                }
+                ConnectTraceKind::Handshake { endpoint, outcome } => {
+                    let fault = match outcome {
+                        HandshakeOutcome::Established => HandshakeFault::Success { latency_ms },
+                        HandshakeOutcome::CertVerification => {
+                            HandshakeFault::CertVerification { latency_ms }
+                        }
+                        HandshakeOutcome::Timeout => HandshakeFault::Timeout,
+                        HandshakeOutcome::VersionMismatch => {
+                            HandshakeFault::VersionMismatch { latency_ms }
+                        }
+                    };
+                    faults.handshake.entry(*endpoint).or_default().push(fault);
+                }
+            }
+        }
+        faults
+    }
+}
+
+/// Recorded result of one lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveOutcome {
+    /// The zone's addresses for the name.
+    Answer(Vec<IpAddr>),
+    /// The name does not exist.
+    NxDomain,
+    /// The server failed to produce an answer.
+    ServFail,
+    /// The lookup hit the resolve timeout.
+    Timeout,
+}
+
+/// Recorded result of one handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeOutcome {
+    /// The handshake completed.
+    Established,
+    /// The server's certificate failed verification.
+    CertVerification,
+    /// The handshake hit the handshake timeout.
+    Timeout,
+    /// Client and server shared no protocol version.
+    VersionMismatch,
+}
+
+/// One lookup or handshake observed by a [`ConnectSim`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectTraceEvent {
+    /// Virtual time the phase started.
+    pub time: Time,
+    /// Virtual time the phase took.
+    pub latency_ms: u64,
+    /// What happened.
+    pub kind: ConnectTraceKind,
+}
+
+/// Connection-establishment trace event kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectTraceKind {
+    /// A lookup of `name`.
+    Resolve {
+        /// Name looked up.
+        name: String,
+        /// Its result.
+        outcome: ResolveOutcome,
+    },
+    /// A handshake with `endpoint`.
+    Handshake {
+        /// Endpoint dialed.
+        endpoint: SocketAddr,
+        /// Its result.
+        outcome: HandshakeOutcome,
+    },
+}
+
+/// Latencies, timeouts, and seed of a [`ConnectSim`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectSimConfig {
+    /// Seed for the latency jitter of unscripted phases.
+    pub seed: u64,
+    /// Latency of an unscripted lookup.
+    pub resolve_latency_ms: u64,
+    /// Latency of an unscripted handshake.
+    pub handshake_latency_ms: u64,
+    /// Upper bound of the uniform jitter added to unscripted latencies.
+    pub jitter_ms: u64,
+    /// Lookups slower than this fail as timeouts.
+    pub resolve_timeout_ms: u64,
+    /// Handshakes slower than this fail as timeouts.
+    pub handshake_timeout_ms: u64,
+    /// TTL of every zone answer.
+    pub ttl: Duration,
+}
+
+impl Default for ConnectSimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            resolve_latency_ms: 2,
+            handshake_latency_ms: 10,
+            jitter_ms: 0,
+            resolve_timeout_ms: 5_000,
+            handshake_timeout_ms: 10_000,
+            ttl: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ConnectSimConfig {
+    /// Sets the jitter seed.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the jitter bound.
+    #[must_use]
+    pub const fn with_jitter_ms(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    fn jittered(&self, base_ms: u64, rng: &mut DetRng) -> u64 {
+        if self.jitter_ms == 0 {
+            return base_ms;
+        }
+        base_ms.saturating_add(rng.next_u64() % (self.jitter_ms + 1))
+    }
+}
+
+/// Scripted DNS resolver over a static zone.
+#[derive(Debug, Default)]
+pub struct SimResolver {
+    zone: BTreeMap<String, Vec<IpAddr>>,
+    script: BTreeMap<String, VecDeque<ResolveFault>>,
+}
+
+impl SimResolver {
+    /// Sets the addresses `name` resolves to. Names outside the zone answer
+    /// NXDOMAIN.
+    pub fn set_zone(&mut self, name: impl Into<String>, addrs: impl IntoIterator<Item = IpAddr>) {
+        self.zone.insert(name.into(), addrs.into_iter().collect());
+    }
+
+    /// Appends `faults` to the script for `name`.
+    pub fn script(
+        &mut self,
+        name: impl Into<String>,
+        faults: impl IntoIterator<Item = ResolveFault>,
+    ) {
+        self.script.entry(name.into()).or_default().extend(faults);
+    }
+
+    /// Scripted lookups of `name` not yet consumed.
+    #[must_use]
+    pub fn pending(&self, name: &str) -> usize {
+        self.script.get(name).map_or(0, VecDeque::len)
+    }
+
+    fn lookup(
+        &mut self,
+        name: &str,
+        config: &ConnectSimConfig,
+        rng: &mut DetRng,
+    ) -> (ResolveOutcome, u64) {
+        let scripted = self.script.get_mut(name).and_then(VecDeque::pop_front);
+        let answer = || match self.zone.get(name) {
+            Some(addrs) => ResolveOutcome::Answer(addrs.clone()),
+            None => ResolveOutcome::NxDomain,
+        };
+        let (outcome, latency_ms) = match scripted {
+            Some(ResolveFault::NxDomain { latency_ms }) => (ResolveOutcome::NxDomain, latency_ms),
+            Some(ResolveFault::ServFail { latency_ms }) => (ResolveOutcome::ServFail, latency_ms),
+            Some(ResolveFault::Timeout) => (ResolveOutcome::Timeout, config.resolve_timeout_ms),
+            Some(ResolveFault::Slow { latency_ms }) => (answer(), latency_ms),
+            None => (answer(), config.jittered(config.resolve_latency_ms, rng)),
+        };
+        if latency_ms > config.resolve_timeout_ms {
+            (ResolveOutcome::Timeout, config.resolve_timeout_ms)
+        } else {
+            (outcome, latency_ms)
+        }
+    }
+}
+
+/// Scripted TLS handshakes per endpoint.
+#[derive(Debug, Default)]
+pub struct SimTls {
+    script: BTreeMap<SocketAddr, VecDeque<HandshakeFault>>,
+}
+
+impl SimTls {
+    /// Appends `faults` to the script for `endpoint`.
+    pub fn script(
+        &mut self,
+        endpoint: SocketAddr,
+        faults: impl IntoIterator<Item = HandshakeFault>,
+    ) {
+        self.script.entry(endpoint).or_default().extend(faults);
+    }
+
+    /// Scripted handshakes with `endpoint` not yet consumed.
+    #[must_use]
+    pub fn pending(&self, endpoint: SocketAddr) -> usize {
+        self.script.get(&endpoint).map_or(0, VecDeque::len)
+    }
+
+    fn handshake(
+        &mut self,
+        endpoint: SocketAddr,
+        config: &ConnectSimConfig,
+        rng: &mut DetRng,
+    ) -> (HandshakeOutcome, u64) {
+        let scripted = self
+            .script
+            .get_mut(&endpoint)
+            .and_then(VecDeque::pop_front);
+        let (outcome, latency_ms) = match scripted {
+            Some(HandshakeFault::Success { latency_ms }) => {
+                (HandshakeOutcome::Established, latency_ms)
+            }
+            Some(HandshakeFault::CertVerification { latency_ms }) => {
+                (HandshakeOutcome::CertVerification, latency_ms)
+            }
+            Some(HandshakeFault::Timeout) => {
+                (HandshakeOutcome::Timeout, config.handshake_timeout_ms)
+            }
+            Some(HandshakeFault::VersionMismatch { latency_ms }) => {
+                (HandshakeOutcome::VersionMismatch, latency_ms)
+            }
+            None => (
+                HandshakeOutcome::Established,
+                config.jittered(config.handshake_latency_ms, rng),
+            ),
+        };
+        if latency_ms > config.handshake_timeout_ms {
+            (HandshakeOutcome::Timeout, config.handshake_timeout_ms)
+        } else {
+            (outcome, latency_ms)
+        }
+    }
+}
+
+/// A named upstream: the endpoint set a client balances across and the DNS
+/// source that keeps it current, both on the simulator's clock.
+#[derive(Debug)]
+pub struct SimUpstream {
+    host: String,
+    endpoints: EndpointSet,
+    source: DnsEndpointSource,
+}
+
+impl SimUpstream {
+    /// Name the upstream resolves.
+    #[must_use]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The balanced endpoint set.
+    #[must_use]
+    pub const fn endpoints(&self) -> &EndpointSet {
+        &self.endpoints
+    }
+
+    /// The DNS source feeding the endpoint set.
+    #[must_use]
+    pub const fn source(&self) -> &DnsEndpointSource {
+        &self.source
+    }
+}
+
+/// Deterministic simulation of name resolution and TLS handshakes.
+#[derive(Debug)]
+pub struct ConnectSim {
+    config: ConnectSimConfig,
+    clock: Arc<AtomicU64>,
+    rng: DetRng,
+    resolver: SimResolver,
+    tls: SimTls,
+    trace: Vec<ConnectTraceEvent>,
+}
+
+impl ConnectSim {
+    /// Creates a simulator at virtual time zero with the scripts in `faults`.
+    #[must_use]
+    pub fn new(config: ConnectSimConfig, faults: &ConnectFaults) -> Self {
+        let mut sim = Self {
+            rng: DetRng::new(config.seed),
+            config,
+            clock: Arc::new(AtomicU64::new(0)),
+            resolver: SimResolver::default(),
+            tls: SimTls::default(),
+            trace: Vec::new(),
+        };
+        for (name, script) in &faults.resolve {
+            sim.resolver.script(name.clone(), script.iter().copied());
+        }
+        for (endpoint, script) in &faults.handshake {
+            sim.tls.script(*endpoint, script.iter().copied());
+        }
+        sim
+    }
+
+    /// The simulated resolver.
+    #[must_use]
+    pub const fn resolver(&self) -> &SimResolver {
+        &self.resolver
+    }
+
+    /// The simulated resolver, for editing its zone and scripts.
+    pub const fn resolver_mut(&mut self) -> &mut SimResolver {
+        &mut self.resolver
+    }
+
+    /// The simulated TLS layer.
+    #[must_use]
+    pub const fn tls(&self) -> &SimTls {
+        &self.tls
+    }
+
+    /// The simulated TLS layer, for editing its scripts.
+    pub const fn tls_mut(&mut self) -> &mut SimTls {
+        &mut self.tls
+    }
+
+    /// Current virtual time.
+    #[must_use]
+    pub fn now(&self) -> Time {
+        Time::from_nanos(self.clock.load(Ordering::Acquire))
+    }
+
+    /// Advances virtual time by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.advance_nanos(nanos);
+    }
+
+    fn advance_nanos(&self, nanos: u64) {
+        let now = self.clock.load(Ordering::Acquire);
+        self.clock.store(now.saturating_add(nanos), Ordering::Release);
+    }
+
+    /// A time getter reading the simulator's clock.
+    #[must_use]
+    pub fn time_getter(&self) -> impl Fn() -> Time + Send + Sync + 'static {
+        let clock = Arc::clone(&self.clock);
+        move || Time::from_nanos(clock.load(Ordering::Acquire))
+    }
+
+    /// Every lookup and handshake so far, in order.
+    #[must_use]
+    pub fn trace(&self) -> &[ConnectTraceEvent] {
+        &self.trace
+    }
+
+    /// Creates an upstream for `host`, dialing `port` on every resolved
+    /// address. `config` is rebound to the simulator's clock.
+    #[must_use]
+    pub fn upstream(
+        &self,
+        host: impl Into<String>,
+        port: u16,
+        config: EndpointSetConfig,
+    ) -> SimUpstream {
+        let host = host.into();
+        let endpoints = EndpointSet::new(config.with_time_getter(self.time_getter()), []);
+        // Lookups come from the simulator; the source only tracks TTLs and
+        // applies answers, so its resolver is never consulted.
+        let source = DnsEndpointSource::new(Resolver::new(), host.clone(), port);
+        SimUpstream {
+            host,
+            endpoints,
+            source,
+        }
+    }
+
+    /// Looks up `name`, advancing virtual time by the lookup's latency.
+    pub fn resolve(&mut self, name: &str) -> Result<LookupIp, ClientError> {
+        let time = self.now();
+        let (outcome, latency_ms) = self.resolver.lookup(name, &self.config, &mut self.rng);
+        self.advance_nanos(latency_ms.saturating_mul(NANOS_PER_MS));
+        let result = match &outcome {
+            ResolveOutcome::Answer(addrs) => Ok(LookupIp::new(addrs.clone(), self.config.ttl)),
+            ResolveOutcome::NxDomain => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("NXDOMAIN for {name}"),
+            )),
+            ResolveOutcome::ServFail => Err(io::Error::other(format!("SERVFAIL for {name}"))),
+            ResolveOutcome::Timeout => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("lookup of {name} timed out"),
+            )),
+        };
+        self.trace.push(ConnectTraceEvent {
+            time,
+            latency_ms,
+            kind: ConnectTraceKind::Resolve {
+                name: name.to_string(),
+                outcome,
+            },
+        });
+        result.map_err(ClientError::DnsError)
+    }
+
+    /// Performs a TLS handshake with `endpoint`, advancing virtual time by
+    /// its latency.
+    pub fn handshake(&mut self, endpoint: SocketAddr) -> Result<(), ClientError> {
+        let time = self.now();
+        let (outcome, latency_ms) = self.tls.handshake(endpoint, &self.config, &mut self.rng);
+        self.advance_nanos(latency_ms.saturating_mul(NANOS_PER_MS));
+        self.trace.push(ConnectTraceEvent {
+            time,
+            latency_ms,
+            kind: ConnectTraceKind::Handshake { endpoint, outcome },
+        });
+        let failure = match outcome {
+            HandshakeOutcome::Established => return Ok(()),
+            HandshakeOutcome::CertVerification => "certificate verification failed",
+            HandshakeOutcome::Timeout => "handshake timed out",
+            HandshakeOutcome::VersionMismatch => "protocol version mismatch",
+        };
+        Err(ClientError::TlsError(format!("{endpoint}: {failure}")))
+    }
+
+    /// Establishes a connection for one `method` request to `upstream`.
+    ///
+    /// Resolves the host first when the previous answer expired. A failed
+    /// lookup leaves the source due for re-resolution on the next call and
+    /// keeps serving the stale endpoints; it only fails the request when no
+    /// endpoint is known. Handshake failures are recorded against their
+    /// endpoint and move to another one within the set's attempt budget.
+    ///
+    /// Returns the lease of the endpoint whose handshake completed; resolve
+    /// it once the request's outcome is known.
+    pub fn establish(
+        &mut self,
+        upstream: &mut SimUpstream,
+        method: &Method,
+    ) -> Result<EndpointLease, ClientError> {
+        if upstream.source.needs_refresh(self.now()) {
+            match self.resolve(&upstream.host) {
+                Ok(lookup) => {
+                    upstream
+                        .source
+                        .apply(&upstream.endpoints, &lookup, self.now());
+                }
+                Err(err) if upstream.endpoints.is_empty() => return Err(err),
+                Err(_) => {}
+            }
+        }
+
+        let mut tried = Vec::new();
+        let mut last_error = None;
+        while tried.len() < upstream.endpoints.max_attempts() as usize {
+            let Some(lease) = upstream.endpoints.pick(&tried) else {
+                break;
+            };
+            tried.push(lease.addr());
+            let Err(err) = self.handshake(lease.addr()) else {
+                return Ok(lease);
+            };
+            match err.retry_class(method) {
+                RetryClass::NextEndpoint => {
+                    lease.fail();
+                    last_error = Some(err);
+                }
+                RetryClass::ReResolve => return Err(err),
+                RetryClass::Fail => {
+                    lease.fail();
+                    return Err(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ClientError::ConnectError(io::Error::new(
+                io::ErrorKind::NotConnected,
+                format!("no endpoints available for {}", upstream.host),
+            ))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::http::h1::RequestPhase;
+    use crate::net::endpoint_set::EjectionConfig;
+    use std::net::Ipv4Addr;
+
+    const HOST: &str = "api.internal";
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+    }
+
+    fn endpoint(last: u8) -> SocketAddr {
+        SocketAddr::new(ip(last), 443)
+    }
+
+    fn set_config() -> EndpointSetConfig {
+        EndpointSetConfig::default()
+            .with_ejection(EjectionConfig {
+                consecutive_failures: 2,
+                base_cooldown: Duration::from_secs(10),
+                max_cooldown: Duration::from_secs(60),
+                jitter: 0.0,
+            })
+            .with_max_attempts(3)
+    }
+
+    fn sim(config: ConnectSimConfig, faults: &ConnectFaults) -> (ConnectSim, SimUpstream) {
+        let mut sim = ConnectSim::new(config, faults);
+        sim.resolver_mut().set_zone(HOST, [ip(1), ip(2), ip(3)]);
+        let upstream = sim.upstream(HOST, 443, set_config());
+        (sim, upstream)
+    }
+
+    /// Runs `requests` GETs, succeeding every established lease.
+    fn drive(sim: &mut ConnectSim, upstream: &mut SimUpstream, requests: usize) -> Vec<String> {
+        let mut results = Vec::new();
+        for _ in 0..requests {
+            match sim.establish(upstream, &Method::Get) {
+                Ok(lease) => {
+                    results.push(lease.addr().to_string());
+                    lease.succeed();
+                }
+                Err(err) => results.push(err.to_string()),
+            }
+            sim.advance(Duration::from_secs(1));
+        }
+        results
+    }
+
+    fn resolves(sim: &ConnectSim) -> usize {
+        sim.trace()
+            .iter()
+            .filter(|event| matches!(event.kind, ConnectTraceKind::Resolve { .. }))
+            .count()
+    }
+
+    #[test]
+    fn handshake_failures_eject_while_resolve_failures_re_resolve() {
+        init_test("handshake_failures_eject_while_resolve_failures_re_resolve");
+        let cert = HandshakeFault::CertVerification { latency_ms: 3 };
+        let mut faults = ConnectFaults::default();
+        faults.handshake.insert(endpoint(1), vec![cert, cert]);
+        let (mut sim, mut upstream) = sim(ConnectSimConfig::default(), &faults);
+
+        // Two certificate failures on 10.0.0.1 eject it; each request moves on.
+        for _ in 0..4 {
+            let lease = sim.establish(&mut upstream, &Method::Get).expect("fails over");
+            crate::assert_with_log!(
+                lease.addr() != endpoint(1),
+                "handshake failures move on",
+                "not 10.0.0.1",
+                lease.addr()
+            );
+            lease.succeed();
+        }
+        let metrics = upstream.endpoints().metrics();
+        let ejected = metrics[0].ejected_until.is_some() && metrics[0].failures == 2;
+        crate::assert_with_log!(ejected, "handshake ejects", true, metrics[0]);
+
+        // Once the TTL expires, a SERVFAIL keeps the stale endpoints, touches
+        // no endpoint's health, and the next request resolves again.
+        sim.resolver_mut()
+            .script(HOST, [ResolveFault::ServFail { latency_ms: 4 }]);
+        sim.advance(Duration::from_secs(30));
+        let before = resolves(&sim);
+        let lease = sim.establish(&mut upstream, &Method::Get).expect("stale set");
+        lease.succeed();
+        let failures: u64 = upstream
+            .endpoints()
+            .metrics()
+            .iter()
+            .map(|metrics| metrics.failures)
+            .sum();
+        crate::assert_with_log!(failures == 2, "resolve failure spares health", 2, failures);
+        let due = upstream.source().needs_refresh(sim.now());
+        crate::assert_with_log!(due, "still due for re-resolution", true, due);
+        let lease = sim.establish(&mut upstream, &Method::Get).expect("re-resolved");
+        lease.succeed();
+        let lookups = resolves(&sim) - before;
+        crate::assert_with_log!(lookups == 2, "re-resolution", 2, lookups);
+        let due = upstream.source().needs_refresh(sim.now());
+        crate::assert_with_log!(!due, "fresh answer applied", false, due);
+        crate::test_complete!("handshake_failures_eject_while_resolve_failures_re_resolve");
+    }
+
+    #[test]
+    fn errors_carry_their_phase_and_retry_class() {
+        init_test("errors_carry_their_phase_and_retry_class");
+        let mut faults = ConnectFaults::default();
+        faults.resolve.insert(
+            HOST.to_string(),
+            vec![ResolveFault::NxDomain { latency_ms: 1 }, ResolveFault::Timeout],
+        );
+        let version = HandshakeFault::VersionMismatch { latency_ms: 2 };
+        for last in 1..=3 {
+            faults.handshake.insert(endpoint(last), vec![version]);
+        }
+        let (mut sim, mut upstream) = sim(ConnectSimConfig::default(), &faults);
+
+        // With nothing resolved yet, resolve failures fail the request.
+        for _ in 0..2 {
+            let err = sim
+                .establish(&mut upstream, &Method::Post)
+                .expect_err("no endpoints known");
+            crate::assert_with_log!(
+                err.phase() == Some(RequestPhase::Resolve),
+                "resolve phase",
+                "resolve",
+                err
+            );
+            let class = err.retry_class(&Method::Post);
+            crate::assert_with_log!(class == RetryClass::ReResolve, "class", "ReResolve", class);
+        }
+        let timeout = sim.trace()[1].latency_ms;
+        crate::assert_with_log!(timeout == 5_000, "timeout latency", 5_000, timeout);
+
+        // Every endpoint refuses the protocol version: the attempt budget is
+        // spent on handshakes, each of which asks for another endpoint.
+        let err = sim
+            .establish(&mut upstream, &Method::Post)
+            .expect_err("all handshakes fail");
+        crate::assert_with_log!(
+            err.phase() == Some(RequestPhase::Handshake),
+            "handshake phase",
+            "handshake",
+            err
+        );
+        let class = err.retry_class(&Method::Post);
+        crate::assert_with_log!(class == RetryClass::NextEndpoint, "class", "NextEndpoint", class);
+        let handshakes = sim.trace().len() - 3;
+        crate::assert_with_log!(handshakes == 3, "attempt budget", 3, handshakes);
+        crate::test_complete!("errors_carry_their_phase_and_retry_class");
+    }
+
+    #[test]
+    fn seeded_runs_reproduce_and_traces_replay_exactly() {
+        init_test("seeded_runs_reproduce_and_traces_replay_exactly");
+        let mut faults = ConnectFaults::default();
+        faults.resolve.insert(
+            HOST.to_string(),
+            vec![ResolveFault::Slow { latency_ms: 9_000 }],
+        );
+        faults.handshake.insert(
+            endpoint(2),
+            vec![
+                HandshakeFault::Timeout,
+                HandshakeFault::CertVerification { latency_ms: 1 },
+            ],
+        );
+
+        let mut runs = Vec::new();
+        for seed in [1, 7, 42] {
+            let config = ConnectSimConfig::default()
+                .with_seed(seed)
+                .with_jitter_ms(25);
+            let (mut first, mut first_upstream) = sim(config, &faults);
+            let (mut second, mut second_upstream) = sim(config, &faults);
+            let results = drive(&mut first, &mut first_upstream, 40);
+            let replayed = drive(&mut second, &mut second_upstream, 40);
+            crate::assert_with_log!(results == replayed, "same seed", results, replayed);
+            crate::assert_with_log!(
+                first.trace() == second.trace(),
+                "same trace",
+                first.trace().len(),
+                second.trace().len()
+            );
+
+            // A script rebuilt from the trace reproduces it without jitter.
+            let script = ConnectFaults::from_trace(first.trace());
+            let (mut replay, mut replay_upstream) = sim(ConnectSimConfig::default(), &script);
+            let replay_results = drive(&mut replay, &mut replay_upstream, 40);
+            crate::assert_with_log!(
+                replay_results == results,
+                "replayed results",
+                results,
+                replay_results
+            );
+            crate::assert_with_log!(
+                replay.trace() == first.trace(),
+                "replayed trace",
+                first.trace().len(),
+                replay.trace().len()
+            );
+            runs.push(first.trace().to_vec());
+        }
+        let distinct = runs[0] != runs[1] && runs[1] != runs[2];
+        crate::assert_with_log!(distinct, "seeds differ", true, distinct);
+        crate::test_complete!("seeded_runs_reproduce_and_traces_replay_exactly");
+    }
+}
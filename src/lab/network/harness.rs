@@ -35,20 +35,52 @@
 //! at(200ms) → CrashNode(C)
 //! at(800ms) → RestartNode(C)
 //! at(300ms) → ExpireLeases(A)
+//! at(400ms) → SetClockOffset(B, +5s)
 //! ```
+//!
+//! # Clocks
+//!
+//! Every node reads simulation time through its own [`ClockOffset`], so
+//! cross-node timing logic (lease expiry, idempotency TTLs) can be exercised
+//! under injected skew. Nodes act as lease holders and grantors: a holder
+//! requests a lease with [`DistributedHarness::request_lease`] and renews it
+//! a margin before its locally computed expiry; the grantor renews or grants
+//! it on its own clock (see [`HeldLease`]).
+//!
+//! # Authorization
+//!
+//! Each node admits peers through a [`PeerGate`]. The first message from a
+//! peer opens a connection with the identity scripted by
+//! [`DistributedHarness::set_peer_identity`] (a bare cluster member by
+//! default); a node configured with [`DistributedHarness::set_authorizer`]
+//! refuses connections and capabilities according to its policy, so policy
+//! matrices can be exercised without a real transport.
+//!
+//! # Payload compression
+//!
+//! Nodes enabled with [`DistributedHarness::set_compression`] negotiate
+//! payload compression the first time they exchange a message (see
+//! [`RemoteCompression`]); a node without it behaves like a peer that
+//! predates compression. Virtual tasks echo their input as their result, so
+//! payloads cross each connection in both directions.
 
 use super::network::MAX_DUPLICATE_PACKET_DELAY;
 use crate::bytes::Bytes;
+use crate::codec::compression::{CompressionConfig, CompressionError};
 use crate::cx::Cx;
+use crate::distributed::authz::{
+    AllowAll, Authorizer, AuthzAuditEvent, AuthzDenied, PeerGate, PeerIdentity, RemoteCapability,
+};
 use crate::lab::network::{DeterministicNetwork, Fault, HostId, NetworkConfig};
 use crate::remote::{
-    CancelRequest, IdempotencyKey, IdempotencyRequestFingerprint, IdempotencyStore, LeaseRenewal,
-    MessageEnvelope, NodeId, RemoteCap, RemoteError, RemoteMessage, RemoteOutcome, RemoteRuntime,
-    RemoteTaskId, RemoteTaskState, ResultDelivery, SpawnAck, SpawnAckStatus, SpawnRejectReason,
-    SpawnRequest,
+    CancelRequest, ClockSkewConfig, ClockSkewWarning, HeldLease, IdempotencyKey,
+    IdempotencyRequestFingerprint, IdempotencyStore, Lease, LeaseRenewal, MessageEnvelope, NodeId,
+    PeerClock, RemoteCap, RemoteCompression, RemoteError, RemoteInput, RemoteMessage,
+    RemoteOutcome, RemoteRuntime, RemoteTaskId, RemoteTaskState, ResultDelivery, SpawnAck,
+    SpawnAckStatus, SpawnRejectReason, SpawnRequest,
 };
 use crate::trace::distributed::{CausalTracker, LogicalTime, VectorClock};
-use crate::types::{Budget, RegionId, TaskId, Time};
+use crate::types::{Budget, ObligationId, RegionId, TaskId, Time};
 use parking_lot::Mutex;
 use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
@@ -83,6 +115,61 @@ const fn harness_origin_task() -> TaskId {
     TaskId::testing_default()
 }
 
+/// Injected offset of a node's clock from simulation time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClockOffset {
+    nanos: i64,
+}
+
+impl ClockOffset {
+    /// No offset: the node reads simulation time.
+    pub const ZERO: Self = Self { nanos: 0 };
+
+    /// A clock running `by` ahead of simulation time.
+    #[must_use]
+    pub fn ahead(by: Duration) -> Self {
+        Self {
+            nanos: i64::try_from(by.as_nanos()).unwrap_or(i64::MAX),
+        }
+    }
+
+    /// A clock running `by` behind simulation time.
+    #[must_use]
+    pub fn behind(by: Duration) -> Self {
+        Self {
+            nanos: -Self::ahead(by).nanos,
+        }
+    }
+
+    /// Returns the signed offset in nanoseconds.
+    #[must_use]
+    pub const fn as_nanos(self) -> i64 {
+        self.nanos
+    }
+
+    /// Reads simulation time through this offset, saturating at zero.
+    #[must_use]
+    pub fn apply(self, sim_time: Time) -> Time {
+        Time::from_nanos(sim_time.as_nanos().saturating_add_signed(self.nanos))
+    }
+}
+
+/// A lease this node granted.
+#[derive(Debug)]
+struct GrantedLease {
+    holder: NodeId,
+    lease: Lease,
+}
+
+/// A lease this node holds or is trying to acquire.
+#[derive(Debug)]
+struct HeldLeaseEntry {
+    lease: HeldLease,
+    duration: Duration,
+    /// Local time of the outstanding request, if any.
+    requested_at: Option<Time>,
+}
+
 #[derive(Clone, Debug)]
 struct StoredEnvelope {
     envelope: MessageEnvelope<RemoteMessage>,
@@ -195,6 +282,20 @@ pub struct SimNode {
     causal: CausalTracker,
     /// Whether this node is crashed.
     crashed: bool,
+    /// Offset of this node's clock from simulation time.
+    clock_offset: ClockOffset,
+    /// Skew tolerance for lease timing.
+    skew_config: ClockSkewConfig,
+    /// Clock views of lease holders, used to answer their renewals.
+    peer_clocks: BTreeMap<NodeId, PeerClock>,
+    /// Leases granted by this node.
+    granted_leases: BTreeMap<RemoteTaskId, GrantedLease>,
+    /// Leases held by (or requested by) this node.
+    held_leases: BTreeMap<RemoteTaskId, HeldLeaseEntry>,
+    /// Admitted peer connections and the authorization policy.
+    gate: PeerGate,
+    /// Payload compression state, if this node supports it.
+    compression: Option<RemoteCompression>,
     /// Node event log for assertions.
     event_log: Vec<NodeEvent>,
 }
@@ -208,6 +309,9 @@ pub struct RunningTask {
     pub idempotency_key: IdempotencyKey,
     /// Origin node that spawned this task.
     pub origin: NodeId,
+    /// Input the task was spawned with. Virtual tasks echo it as their
+    /// output.
+    pub input: RemoteInput,
     /// Virtual work remaining (in time units).
     pub work_remaining: Duration,
     /// Whether a cancellation has been requested.
@@ -251,16 +355,62 @@ pub enum NodeEvent {
         /// Task id for the cancellation request.
         task_id: RemoteTaskId,
     },
+    /// Rejected a cancellation for a task this node is not running.
+    ///
+    /// Remote task ids are never reused, so such a request names a task that
+    /// already finished (or never arrived) and is dropped rather than applied.
+    StaleCancelRejected {
+        /// Task id named by the rejected request.
+        task_id: RemoteTaskId,
+    },
     /// Received a lease renewal.
     LeaseRenewed {
         /// Task id for the lease renewal.
         task_id: RemoteTaskId,
     },
+    /// Granted or renewed a lease as its grantor.
+    LeaseGranted {
+        /// Task id of the lease.
+        task_id: RemoteTaskId,
+        /// Node now holding the lease.
+        holder: NodeId,
+    },
+    /// A peer's clock disagrees beyond the skew tolerance.
+    ClockSkew {
+        /// The structured warning.
+        warning: ClockSkewWarning,
+    },
     /// Duplicate spawn detected.
     DuplicateSpawn {
         /// Task id that was duplicated.
         task_id: RemoteTaskId,
     },
+    /// Refused a connection; nothing the peer sent was processed.
+    ConnectionRefused {
+        /// Peer whose connection was refused.
+        peer: NodeId,
+        /// Denial reason.
+        reason: String,
+    },
+    /// Dropped a message exercising a capability its sender lacks.
+    ///
+    /// Denied spawns are answered and logged as [`NodeEvent::SpawnRejected`]
+    /// instead.
+    CapabilityDenied {
+        /// Sender of the message.
+        from: NodeId,
+        /// Capability the message exercised.
+        capability: RemoteCapability,
+        /// Denial reason.
+        reason: String,
+    },
+    /// Dropped a message whose compressed payload could not be decoded.
+    PayloadRejected {
+        /// Sender of the message.
+        from: NodeId,
+        /// Why the payload was rejected.
+        error: CompressionError,
+    },
     /// Node crashed.
     Crashed,
     /// Node restarted.
@@ -283,10 +433,117 @@ impl SimNode {
             pending_results: Arc::new(Mutex::new(BTreeMap::new())),
             dedup: IdempotencyStore::new(Duration::from_mins(5)),
             crashed: false,
+            clock_offset: ClockOffset::ZERO,
+            skew_config: ClockSkewConfig::default(),
+            peer_clocks: BTreeMap::new(),
+            granted_leases: BTreeMap::new(),
+            held_leases: BTreeMap::new(),
+            gate: PeerGate::new(Arc::new(AllowAll)),
+            compression: None,
             event_log: Vec::new(),
         }
     }
 
+    /// Replaces the authorization policy. Existing connections are dropped
+    /// and must be admitted again under the new policy.
+    pub fn set_authorizer(&mut self, authorizer: Arc<dyn Authorizer>) {
+        self.gate = PeerGate::new(authorizer);
+    }
+
+    /// Handles an authenticated connection from `identity.node`, asking the
+    /// authorizer whether to admit it. Returns false, logging
+    /// [`NodeEvent::ConnectionRefused`], if the connection is refused or
+    /// this node is down.
+    pub fn accept_connection(&mut self, identity: PeerIdentity, sim_now: Time) -> bool {
+        if self.crashed {
+            return false;
+        }
+        let peer = identity.node.clone();
+        let now = self.local_time(sim_now);
+        match self.gate.admit(identity, now) {
+            Ok(()) => true,
+            Err(denied) => {
+                self.event_log.push(NodeEvent::ConnectionRefused {
+                    peer,
+                    reason: denied.reason,
+                });
+                false
+            }
+        }
+    }
+
+    /// Enables payload compression on this node's connections. Peers that
+    /// never enable it are treated as predating compression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Unavailable`] if the configured algorithm
+    /// is not compiled into this build.
+    pub fn set_compression(&mut self, config: CompressionConfig) -> Result<(), CompressionError> {
+        self.compression = Some(RemoteCompression::new(config)?);
+        Ok(())
+    }
+
+    /// Returns this node's payload compression state, if enabled.
+    #[must_use]
+    pub fn compression(&self) -> Option<&RemoteCompression> {
+        self.compression.as_ref()
+    }
+
+    /// Returns true if `peer` has an admitted connection to this node.
+    #[must_use]
+    pub fn is_connected(&self, peer: &NodeId) -> bool {
+        self.gate.is_admitted(peer)
+    }
+
+    /// Returns the authorization decisions audited by this node.
+    #[must_use]
+    pub fn authz_audit(&self) -> &[AuthzAuditEvent] {
+        self.gate.audit()
+    }
+
+    /// Returns the offset of this node's clock from simulation time.
+    #[must_use]
+    pub fn clock_offset(&self) -> ClockOffset {
+        self.clock_offset
+    }
+
+    /// Sets the offset of this node's clock. Takes effect immediately, like
+    /// a clock step.
+    pub fn set_clock_offset(&mut self, offset: ClockOffset) {
+        self.clock_offset = offset;
+    }
+
+    /// Sets the skew tolerance used for leases created from now on.
+    pub fn set_clock_skew_config(&mut self, config: ClockSkewConfig) {
+        self.skew_config = config;
+    }
+
+    /// Reads simulation time `sim_now` on this node's clock.
+    #[must_use]
+    pub fn local_time(&self, sim_now: Time) -> Time {
+        self.clock_offset.apply(sim_now)
+    }
+
+    /// Returns this node's claim on the lease for `task_id`, if it made one.
+    #[must_use]
+    pub fn held_lease(&self, task_id: RemoteTaskId) -> Option<&HeldLease> {
+        self.held_leases.get(&task_id).map(|entry| &entry.lease)
+    }
+
+    /// Starts acquiring the lease for `task_id` from `grantor`, renewing it
+    /// for `duration` at a time until the node crashes.
+    pub fn request_lease(&mut self, grantor: NodeId, task_id: RemoteTaskId, duration: Duration) {
+        self.held_leases.insert(
+            task_id,
+            HeldLeaseEntry {
+                lease: HeldLease::new(task_id, grantor, self.skew_config),
+                duration,
+                requested_at: None,
+            },
+        );
+    }
+
     /// Creates a RemoteCap connected to this node.
     #[must_use]
     pub fn create_cap(&self) -> RemoteCap {
@@ -305,15 +562,65 @@ impl SimNode {
         if self.crashed {
             return; // Silently drop messages to crashed nodes
         }
+        if !self.gate.is_admitted(&envelope.sender) {
+            return; // No connection: nothing from this peer is processed
+        }
 
-        self.record_receive(&envelope.sender_time);
+        // Node logic only ever sees its own clock.
+        let now = self.local_time(now);
+        if let Err(denied) = self.gate.check(&envelope, now) {
+            self.refuse(envelope, denied);
+            return;
+        }
 
-        match envelope.payload {
+        let mut payload = envelope.payload;
+        if let Some(compression) = &self.compression {
+            match compression.decode_inbound(&envelope.sender, payload) {
+                Ok(decoded) => payload = decoded,
+                Err(error) => {
+                    self.event_log.push(NodeEvent::PayloadRejected {
+                        from: envelope.sender,
+                        error,
+                    });
+                    return;
+                }
+            }
+        }
+
+        self.record_receive(&envelope.sender_time);
+        match payload {
             RemoteMessage::SpawnRequest(req) => self.handle_spawn(req, now),
             RemoteMessage::SpawnAck(ack) => self.handle_spawn_ack(ack),
             RemoteMessage::CancelRequest(cancel) => self.handle_cancel(&cancel),
             RemoteMessage::ResultDelivery(result) => self.handle_result(result),
-            RemoteMessage::LeaseRenewal(renewal) => self.handle_lease_renewal(&renewal),
+            RemoteMessage::LeaseRenewal(renewal) => {
+                self.handle_lease_renewal(&envelope.sender, &renewal, now);
+            }
+        }
+    }
+
+    fn refuse(&mut self, envelope: MessageEnvelope<RemoteMessage>, denied: AuthzDenied) {
+        match envelope.payload {
+            RemoteMessage::SpawnRequest(req) => {
+                let reason = SpawnRejectReason::Unauthorized(denied.reason);
+                self.outbox.push_back((
+                    req.origin_node,
+                    RemoteMessage::SpawnAck(SpawnAck {
+                        remote_task_id: req.remote_task_id,
+                        status: SpawnAckStatus::Rejected(reason.clone()),
+                        assigned_node: self.node_id.clone(),
+                    }),
+                ));
+                self.event_log.push(NodeEvent::SpawnRejected {
+                    task_id: req.remote_task_id,
+                    reason,
+                });
+            }
+            payload => self.event_log.push(NodeEvent::CapabilityDenied {
+                from: envelope.sender,
+                capability: RemoteCapability::of(&payload),
+                reason: denied.reason,
+            }),
         }
     }
 
@@ -399,6 +706,7 @@ impl SimNode {
             task_id: req.remote_task_id,
             idempotency_key: req.idempotency_key,
             origin: req.origin_node.clone(),
+            input: req.input,
             work_remaining: Duration::from_millis(100), // Default virtual work
             cancel_requested: false,
         };
@@ -453,6 +761,10 @@ impl SimNode {
         let canonical_task_id = self.canonical_task_id(cancel.remote_task_id);
         if let Some(task) = self.running_tasks.get_mut(&canonical_task_id) {
             task.cancel_requested = true;
+        } else {
+            self.event_log.push(NodeEvent::StaleCancelRejected {
+                task_id: cancel.remote_task_id,
+            });
         }
     }
 
@@ -486,10 +798,102 @@ impl SimNode {
         }
     }
 
-    fn handle_lease_renewal(&mut self, renewal: &LeaseRenewal) {
-        self.event_log.push(NodeEvent::LeaseRenewed {
-            task_id: renewal.remote_task_id,
+    fn handle_lease_renewal(&mut self, from: &NodeId, renewal: &LeaseRenewal, now: Time) {
+        let task_id = renewal.remote_task_id;
+        self.event_log.push(NodeEvent::LeaseRenewed { task_id });
+
+        if let Some(entry) = self
+            .held_leases
+            .get_mut(&task_id)
+            .filter(|entry| entry.lease.grantor() == from)
+        {
+            entry.requested_at = None;
+            if let Some(warning) = entry.lease.on_grant(renewal, now) {
+                self.event_log.push(NodeEvent::ClockSkew { warning });
+            }
+            return;
+        }
+
+        // Grantor side: renew for the current holder, or grant a lease that
+        // is free on this node's clock. Requests for a lease held by another
+        // node go unanswered.
+        let clock = self
+            .peer_clocks
+            .entry(from.clone())
+            .or_insert_with(|| PeerClock::new(from.clone(), self.skew_config));
+        if let Some(warning) = clock.observe(&renewal.timing, now) {
+            self.event_log.push(NodeEvent::ClockSkew { warning });
+        }
+        let renewed = match self.granted_leases.get_mut(&task_id) {
+            Some(granted) if granted.lease.is_active(now) => {
+                granted.holder == *from && granted.lease.renew(renewal.new_lease, now).is_ok()
+            }
+            _ => {
+                let lease = Lease::new(
+                    ObligationId::from_arena(crate::util::ArenaIndex::new(0, 0)),
+                    harness_origin_region(),
+                    harness_origin_task(),
+                    renewal.new_lease,
+                    now,
+                );
+                self.granted_leases.insert(
+                    task_id,
+                    GrantedLease {
+                        holder: from.clone(),
+                        lease,
+                    },
+                );
+                true
+            }
+        };
+        if !renewed {
+            return;
+        }
+        self.event_log.push(NodeEvent::LeaseGranted {
+            task_id,
+            holder: from.clone(),
         });
+        let timing = self.peer_clocks[from].stamp(now);
+        self.outbox.push_back((
+            from.clone(),
+            RemoteMessage::LeaseRenewal(LeaseRenewal {
+                remote_task_id: task_id,
+                new_lease: renewal.new_lease,
+                current_state: RemoteTaskState::Running,
+                node: self.node_id.clone(),
+                timing,
+            }),
+        ));
+    }
+
+    /// Sends lease requests and renewals that are due at simulation time
+    /// `sim_now`.
+    ///
+    /// A renewal is due a margin before the locally computed expiry. An
+    /// unanswered request is repeated once that margin has passed.
+    pub fn poll_leases(&mut self, sim_now: Time) {
+        if self.crashed {
+            return;
+        }
+        let now = self.local_time(sim_now);
+        for entry in self.held_leases.values_mut() {
+            let margin = entry.lease.renewal_margin();
+            let due = entry.lease.renew_at().is_none_or(|renew_at| now >= renew_at);
+            let waiting = entry
+                .requested_at
+                .is_some_and(|requested_at| now < requested_at + margin);
+            if !due || waiting {
+                continue;
+            }
+            entry.requested_at = Some(now);
+            let request = entry
+                .lease
+                .renewal_request(self.node_id.clone(), entry.duration, now);
+            self.outbox.push_back((
+                entry.lease.grantor().clone(),
+                RemoteMessage::LeaseRenewal(request),
+            ));
+        }
     }
 
     /// Advances virtual work on all running tasks by the given duration.
@@ -513,7 +917,7 @@ impl SimNode {
                     .push(NodeEvent::TaskCancelled { task_id: *id });
                 to_remove.push(*id);
             } else if task.work_remaining <= elapsed {
-                let outcome = RemoteOutcome::Success(vec![]);
+                let outcome = RemoteOutcome::Success(task.input.data().to_vec());
                 let _ = self.dedup.complete(&task.idempotency_key, outcome.clone());
                 finalized.push((*id, task.origin.clone(), outcome));
                 self.event_log
@@ -559,6 +963,10 @@ impl SimNode {
         self.duplicate_waiters.clear();
         self.duplicate_aliases.clear();
         self.outbox.clear();
+        self.peer_clocks.clear();
+        self.granted_leases.clear();
+        self.held_leases.clear();
+        self.gate.disconnect_all();
         {
             let mut app = self.app_outbox.lock();
             app.clear();
@@ -616,6 +1024,12 @@ impl SimNode {
         &self.event_log
     }
 
+    /// Returns the running task with `task_id`, if any.
+    #[must_use]
+    pub fn running_task(&self, task_id: RemoteTaskId) -> Option<&RunningTask> {
+        self.running_tasks.get(&task_id)
+    }
+
     /// Returns the number of currently running tasks.
     #[must_use]
     pub fn running_task_count(&self) -> usize {
@@ -659,6 +1073,8 @@ pub enum HarnessFault {
     RestartNode(NodeId),
     /// Force-expire all leases on a node.
     ExpireLeases(NodeId),
+    /// Offset a node's clock from simulation time.
+    SetClockOffset(NodeId, ClockOffset),
 }
 
 /// A script of fault events, sorted by time.
@@ -715,6 +1131,10 @@ pub struct DistributedHarness {
     next_msg_id: u64,
     /// Harness-local message store for side-channel decoding.
     msg_store: BTreeMap<u64, StoredEnvelope>,
+    /// Skew tolerance given to nodes as they are added.
+    skew_config: ClockSkewConfig,
+    /// Identities nodes present when connecting to a peer.
+    identities: BTreeMap<NodeId, PeerIdentity>,
 }
 
 /// A trace event in the harness execution.
@@ -774,6 +1194,8 @@ impl DistributedHarness {
             trace: Vec::new(),
             next_msg_id: 1,
             msg_store: BTreeMap::new(),
+            skew_config: ClockSkewConfig::default(),
+            identities: BTreeMap::new(),
         }
     }
 
@@ -781,13 +1203,30 @@ impl DistributedHarness {
     pub fn add_node(&mut self, name: &str) -> NodeId {
         let node_id = NodeId::new(name);
         let host_id = self.network.add_host(name);
-        let sim_node = SimNode::new(node_id.clone(), host_id);
+        let mut sim_node = SimNode::new(node_id.clone(), host_id);
+        sim_node.set_clock_skew_config(self.skew_config);
         self.nodes.insert(node_id.clone(), sim_node);
         self.node_to_host.insert(node_id.clone(), host_id);
         self.host_to_node.insert(host_id, node_id.clone());
         node_id
     }
 
+    /// Enables payload compression on `node`'s connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Unavailable`] if the configured algorithm
+    /// is not compiled into this build.
+    pub fn set_compression(
+        &mut self,
+        node: &NodeId,
+        config: CompressionConfig,
+    ) -> Result<(), CompressionError> {
+        self.nodes
+            .get_mut(node)
+            .map_or(Ok(()), |node| node.set_compression(config))
+    }
+
     /// Sets the fault script.
     pub fn set_fault_script(&mut self, script: FaultScript) {
         self.fault_script = script;
@@ -798,6 +1237,61 @@ impl DistributedHarness {
         self.tick = normalized_tick(tick);
     }
 
+    /// Sets the lease skew tolerance of every node, present and future.
+    pub fn set_clock_skew_config(&mut self, config: ClockSkewConfig) {
+        self.skew_config = config;
+        for node in self.nodes.values_mut() {
+            node.set_clock_skew_config(config);
+        }
+    }
+
+    /// Offsets `node`'s clock from simulation time.
+    pub fn set_clock_offset(&mut self, node: &NodeId, offset: ClockOffset) {
+        if let Some(node) = self.nodes.get_mut(node) {
+            node.set_clock_offset(offset);
+        }
+    }
+
+    /// Returns the current time on `node`'s clock.
+    #[must_use]
+    pub fn local_time(&self, node: &NodeId) -> Option<Time> {
+        let now = self.sim_now();
+        self.nodes.get(node).map(|node| node.local_time(now))
+    }
+
+    /// Sets the identity `identity.node` presents when it connects to a
+    /// peer. Nodes without one connect as bare cluster members.
+    pub fn set_peer_identity(&mut self, identity: PeerIdentity) {
+        self.identities.insert(identity.node.clone(), identity);
+    }
+
+    /// Sets the authorization policy `node` applies to inbound connections
+    /// and messages.
+    pub fn set_authorizer(&mut self, node: &NodeId, authorizer: Arc<dyn Authorizer>) {
+        if let Some(node) = self.nodes.get_mut(node) {
+            node.set_authorizer(authorizer);
+        }
+    }
+
+    /// Has `holder` acquire and keep renewing the lease for `task_id` from
+    /// `grantor`.
+    pub fn request_lease(
+        &mut self,
+        holder: &NodeId,
+        grantor: &NodeId,
+        task_id: RemoteTaskId,
+        duration: Duration,
+    ) {
+        if let Some(node) = self.nodes.get_mut(holder) {
+            node.request_lease(grantor.clone(), task_id, duration);
+        }
+    }
+
+    fn sim_now(&self) -> Time {
+        let nanos = self.sim_time.as_nanos().min(u128::from(u64::MAX)) as u64;
+        Time::from_nanos(nanos)
+    }
+
     /// Injects a spawn request from `origin` to `target`.
     pub fn inject_spawn(&mut self, origin: &NodeId, target: &NodeId, task_id: RemoteTaskId) {
         let req = SpawnRequest {
@@ -866,7 +1360,8 @@ impl DistributedHarness {
             || LogicalTime::Vector(VectorClock::new()),
             |node| LogicalTime::Vector(node.causal.on_send()),
         );
-        let envelope = MessageEnvelope::new(from.clone(), sender_time, msg.clone());
+        let msg = self.frame_payload(from, to, msg);
+        let envelope = MessageEnvelope::new(from.clone(), sender_time, msg);
 
         // Serialize message as opaque bytes for the deterministic virtual network.
         // In Phase 0, we use a simple encoding: message type tag + task ID.
@@ -874,6 +1369,23 @@ impl DistributedHarness {
         self.network.send(src, dst, Bytes::from(encoded));
     }
 
+    /// Negotiates payload compression for the `from`-`to` connection, as a
+    /// transport handshake would, and frames `msg`'s payload accordingly.
+    fn frame_payload(&self, from: &NodeId, to: &NodeId, msg: &RemoteMessage) -> RemoteMessage {
+        let Some(local) = self.nodes.get(from).and_then(SimNode::compression) else {
+            return msg.clone();
+        };
+        let peer = self.nodes.get(to).and_then(SimNode::compression);
+        let peer_offer = peer.map(RemoteCompression::offer).unwrap_or_default();
+        if let Some(peer) = peer {
+            let _ = peer.negotiate(from, &local.offer());
+        }
+        if local.negotiate(to, &peer_offer).is_none() {
+            return msg.clone();
+        }
+        local.encode_outbound(to, msg.clone())
+    }
+
     /// Runs the simulation for the given duration.
     ///
     /// This advances the deterministic virtual network, delivers messages, processes
@@ -951,14 +1463,21 @@ impl DistributedHarness {
             }
         }
 
-        let now = {
-            let nanos = self.sim_time.as_nanos().min(u128::from(u64::MAX)) as u64;
-            Time::from_nanos(nanos)
-        };
+        let now = self.sim_now();
         for (node_id, envelope) in deliveries {
-            if let Some(node) = self.nodes.get_mut(&node_id) {
-                node.handle_message(envelope, now);
+            let Some(node) = self.nodes.get_mut(&node_id) else {
+                continue;
+            };
+            if !node.is_connected(&envelope.sender) {
+                let identity = self.identities.get(&envelope.sender).map_or_else(
+                    || PeerIdentity::cluster_member(envelope.sender.clone()),
+                    Clone::clone,
+                );
+                if !node.accept_connection(identity, now) {
+                    continue;
+                }
             }
+            node.handle_message(envelope, now);
         }
     }
 
@@ -1034,6 +1553,10 @@ impl DistributedHarness {
         self.sim_time = self.sim_time.saturating_add(elapsed);
         self.deliver_packets();
         self.tick_nodes(elapsed);
+        let now = self.sim_now();
+        for node in self.nodes.values_mut() {
+            node.poll_leases(now);
+        }
         self.flush_outboxes();
         self.prune_decoded_messages();
     }
@@ -1108,6 +1631,11 @@ impl DistributedHarness {
                     }
                 }
             }
+            HarnessFault::SetClockOffset(node_id, offset) => {
+                if let Some(node) = self.nodes.get_mut(node_id) {
+                    node.set_clock_offset(*offset);
+                }
+            }
         }
     }
 
@@ -1181,6 +1709,9 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::codec::compression::CompressionAlgorithm;
+    use crate::distributed::authz::PeerCredentials;
+    use crate::lab::network::NetworkConditions;
 
     fn register_pending_result(
         node: &mut SimNode,
@@ -1472,6 +2003,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cancel_for_finished_task_is_rejected_and_logged() {
+        let (mut harness, a, b) = setup_harness();
+        let task_id = RemoteTaskId::next();
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_millis(500));
+        let completed = |e: &NodeEvent| matches!(e, NodeEvent::TaskCompleted { .. });
+        assert!(harness.node(&b).unwrap().events().iter().any(completed));
+
+        harness.inject_cancel(&a, &b, task_id);
+        harness.run_for(Duration::from_millis(200));
+
+        let events = harness.node(&b).unwrap().events();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            NodeEvent::StaleCancelRejected { task_id: id } if *id == task_id
+        )));
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, NodeEvent::TaskCancelled { .. })),
+            "a stale cancel must not cancel anything"
+        );
+    }
+
     #[test]
     fn partition_prevents_delivery() {
         let config = NetworkConfig {
@@ -2349,4 +2905,480 @@ mod tests {
             panic!("Expected SpawnRequest message");
         }
     }
+
+    fn lease_harness(
+        conditions: NetworkConditions,
+    ) -> (DistributedHarness, NodeId, NodeId, NodeId) {
+        let config = NetworkConfig {
+            default_conditions: conditions,
+            ..NetworkConfig::default()
+        };
+        let mut harness = DistributedHarness::new(config);
+        harness.set_tick(Duration::from_millis(1));
+        let grantor = harness.add_node("grantor");
+        let a = harness.add_node("holder-a");
+        let b = harness.add_node("holder-b");
+        (harness, grantor, a, b)
+    }
+
+    fn holds(harness: &DistributedHarness, node: &NodeId, task_id: RemoteTaskId) -> bool {
+        let now = harness.local_time(node).unwrap();
+        harness
+            .node(node)
+            .unwrap()
+            .held_lease(task_id)
+            .is_some_and(|lease| lease.is_held(now))
+    }
+
+    #[test]
+    fn clock_offset_reads_simulation_time_with_saturation() {
+        let sim = Time::from_secs(10);
+        let ahead = ClockOffset::ahead(Duration::from_secs(5));
+        let behind = ClockOffset::behind(Duration::from_secs(5));
+        assert_eq!(ahead.apply(sim), Time::from_secs(15));
+        assert_eq!(behind.apply(sim), Time::from_secs(5));
+        assert_eq!(behind.as_nanos(), -5_000_000_000);
+        assert_eq!(behind.apply(Time::from_secs(2)), Time::ZERO);
+        assert_eq!(ClockOffset::ZERO.apply(sim), sim);
+    }
+
+    #[test]
+    fn skewed_nodes_never_hold_a_lease_at_the_same_time() {
+        let (mut harness, grantor, a, b) = lease_harness(NetworkConditions::lan());
+        // Tolerate the injected offsets so leases are not shortened and the
+        // invariant rests on local-clock expiry alone.
+        harness.set_clock_skew_config(ClockSkewConfig {
+            max_tolerated_skew: Duration::from_secs(15),
+            ..ClockSkewConfig::default()
+        });
+        harness.set_clock_offset(&grantor, ClockOffset::ahead(Duration::from_secs(5)));
+        harness.set_clock_offset(&a, ClockOffset::behind(Duration::from_secs(5)));
+        harness.set_clock_offset(&b, ClockOffset::ahead(Duration::from_secs(3)));
+        // Let the clock that runs behind leave zero.
+        harness.run_for(Duration::from_secs(6));
+
+        let task_id = RemoteTaskId::from_raw(42);
+        let lease = Duration::from_secs(1);
+        harness.request_lease(&a, &grantor, task_id, lease);
+        harness.run_for(Duration::from_millis(100));
+        harness.request_lease(&b, &grantor, task_id, lease);
+
+        let a_host = harness.node(&a).unwrap().host_id;
+        let grantor_host = harness.node(&grantor).unwrap().host_id;
+        let (mut a_held, mut b_held, mut partitioned) = (false, false, false);
+        while harness.sim_time() < Duration::from_secs(14) {
+            if !partitioned && harness.sim_time() >= Duration::from_secs(9) {
+                // Cut the holder off so the lease lapses and moves to b.
+                harness.execute_fault(&HarnessFault::Network(Fault::Partition {
+                    hosts_a: vec![a_host],
+                    hosts_b: vec![grantor_host],
+                }));
+                partitioned = true;
+            }
+            harness.run_for(Duration::from_millis(1));
+            let in_a = holds(&harness, &a, task_id);
+            let in_b = holds(&harness, &b, task_id);
+            assert!(
+                !(in_a && in_b),
+                "lease held by both holders at {:?}",
+                harness.sim_time()
+            );
+            a_held |= in_a;
+            b_held |= in_b;
+        }
+
+        assert!(a_held && b_held, "lease moved from a to b");
+        let a_grants = harness.node(&a).unwrap().held_lease(task_id).unwrap().grants();
+        assert!(a_grants >= 3, "a renewed across cycles, got {a_grants} grants");
+        let holders: Vec<NodeId> = harness
+            .node(&grantor)
+            .unwrap()
+            .events()
+            .iter()
+            .filter_map(|event| match event {
+                NodeEvent::LeaseGranted { holder, .. } => Some(holder.clone()),
+                _ => None,
+            })
+            .collect();
+        let first_b = holders.iter().position(|holder| *holder == b).unwrap();
+        assert!(holders[..first_b].iter().all(|holder| *holder == a));
+        assert!(holders[first_b..].iter().all(|holder| *holder == b));
+    }
+
+    #[test]
+    fn clock_skew_beyond_tolerance_warns_and_shortens_held_lease() {
+        let (mut harness, grantor, a, _) = lease_harness(NetworkConditions::local());
+        harness.set_clock_offset(&grantor, ClockOffset::ahead(Duration::from_secs(4)));
+        let task_id = RemoteTaskId::from_raw(7);
+        harness.request_lease(&a, &grantor, task_id, Duration::from_secs(5));
+        harness.run_for(Duration::from_millis(50));
+
+        let node_a = harness.node(&a).unwrap();
+        let warning = node_a
+            .events()
+            .iter()
+            .find_map(|event| match event {
+                NodeEvent::ClockSkew { warning } => Some(warning.clone()),
+                _ => None,
+            })
+            .expect("holder warns about the grantor's clock");
+        assert_eq!(warning.peer, grantor);
+        assert!(
+            (3_990_000_000..=4_010_000_000).contains(&warning.skew_nanos),
+            "skew {}",
+            warning.skew_nanos
+        );
+        assert_eq!(warning.tolerated, ClockSkewConfig::default().max_tolerated_skew);
+
+        // The 5s lease is counted as at most 5s - 3s excess.
+        let lease = node_a.held_lease(task_id).unwrap();
+        let now = harness.local_time(&a).unwrap();
+        let remaining = lease.expires_at().unwrap().duration_since(now);
+        assert!(remaining <= 2_000_000_000, "remaining {remaining}ns");
+        assert!(lease.is_held(now));
+
+        let grantor_warned = harness.node(&grantor).unwrap().events().iter().any(
+            |event| matches!(event, NodeEvent::ClockSkew { warning } if warning.skew_nanos < 0),
+        );
+        assert!(grantor_warned, "grantor sees the holder behind");
+    }
+
+    #[test]
+    fn renewals_sent_a_margin_ahead_keep_the_lease_held() {
+        let (mut harness, grantor, a, _) = lease_harness(NetworkConditions::local());
+        harness.set_clock_offset(&a, ClockOffset::ahead(Duration::from_millis(300)));
+        let task_id = RemoteTaskId::from_raw(9);
+        harness.request_lease(&a, &grantor, task_id, Duration::from_millis(500));
+        harness.run_for(Duration::from_millis(20));
+        assert!(holds(&harness, &a, task_id));
+
+        let lease = harness.node(&a).unwrap().held_lease(task_id).unwrap();
+        let margin = lease.renewal_margin();
+        assert!(margin > ClockSkewConfig::default().min_renewal_margin);
+        let renew_at = lease.renew_at().unwrap();
+        assert_eq!(
+            lease.expires_at().unwrap().duration_since(renew_at),
+            margin.as_nanos() as u64
+        );
+
+        for _ in 0..3_000 {
+            harness.run_for(Duration::from_millis(1));
+            assert!(
+                holds(&harness, &a, task_id),
+                "lease lapsed at {:?}",
+                harness.sim_time()
+            );
+        }
+        let grants = harness.node(&a).unwrap().held_lease(task_id).unwrap().grants();
+        assert!(grants >= 6, "renewed every cycle, got {grants} grants");
+    }
+
+    #[test]
+    fn idempotency_expiry_ignores_requester_clock_skew() {
+        let (mut harness, a, b) = setup_harness();
+        harness.set_tick(Duration::from_secs(1));
+        // The requester runs far ahead; the storing node is also offset, so
+        // only durations on b's own clock can produce the 5 minute TTL.
+        harness.set_clock_offset(&a, ClockOffset::ahead(Duration::from_mins(10)));
+        harness.set_clock_offset(&b, ClockOffset::ahead(Duration::from_mins(60)));
+        let task_id = RemoteTaskId::from_raw(8_888);
+        let count = |harness: &DistributedHarness, accepted: bool| {
+            harness
+                .node(&b)
+                .unwrap()
+                .events()
+                .iter()
+                .filter(|event| {
+                    if accepted {
+                        matches!(event, NodeEvent::SpawnAccepted { .. })
+                    } else {
+                        matches!(event, NodeEvent::DuplicateSpawn { .. })
+                    }
+                })
+                .count()
+        };
+
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_secs(2));
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_secs(290));
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_secs(2));
+        assert_eq!(count(&harness, true), 1);
+        assert_eq!(count(&harness, false), 2, "replays within the TTL deduplicate");
+
+        harness.run_for(Duration::from_secs(10));
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_secs(2));
+        assert_eq!(count(&harness, true), 2, "the record expires on b's clock");
+    }
+
+    /// Refuses bare cluster members; token holders may do anything but spawn.
+    #[derive(Debug)]
+    struct TieredPolicy;
+
+    impl Authorizer for TieredPolicy {
+        fn authorize_connection(&self, peer: &PeerIdentity) -> Result<(), AuthzDenied> {
+            if peer.credentials == PeerCredentials::ClusterKey {
+                return Err(AuthzDenied::new("anonymous member"));
+            }
+            Ok(())
+        }
+
+        fn authorize_capability(
+            &self,
+            peer: &PeerIdentity,
+            capability: &RemoteCapability,
+        ) -> Result<(), AuthzDenied> {
+            match (&peer.credentials, capability) {
+                (PeerCredentials::Token { .. }, RemoteCapability::Spawn(_)) => {
+                    Err(AuthzDenied::new("spawn requires a certificate"))
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn token(node: &NodeId) -> PeerIdentity {
+        PeerIdentity::new(node.clone(), PeerCredentials::Token { id: "ci".into() })
+    }
+
+    #[test]
+    fn refused_connection_is_closed_before_processing() {
+        let (mut harness, a, b) = setup_harness();
+        harness.set_authorizer(&b, Arc::new(TieredPolicy));
+        let task_id = RemoteTaskId::next();
+        harness.inject_spawn(&a, &b, task_id);
+        harness.run_for(Duration::from_millis(500));
+
+        let node_b = harness.node(&b).unwrap();
+        assert!(!node_b.is_connected(&a));
+        assert_eq!(node_b.running_task_count(), 0);
+        assert!(!node_b.events().is_empty());
+        for event in node_b.events() {
+            let NodeEvent::ConnectionRefused { peer, reason } = event else {
+                panic!("processed a message from a refused peer: {event:?}");
+            };
+            assert_eq!(*peer, a);
+            assert_eq!(reason, "anonymous member");
+        }
+        // Nothing was answered either.
+        let acks = harness.trace().iter().filter(|event| {
+            matches!(&event.kind, HarnessTraceKind::MessageSent { from, .. } if *from == b)
+        });
+        assert_eq!(acks.count(), 0);
+    }
+
+    #[test]
+    fn denied_spawn_leaves_heartbeats_flowing() {
+        let (mut harness, grantor, a, _) = lease_harness(NetworkConditions::local());
+        harness.set_authorizer(&grantor, Arc::new(TieredPolicy));
+        harness.set_peer_identity(token(&a));
+        let lease_task = RemoteTaskId::from_raw(7);
+        harness.request_lease(&a, &grantor, lease_task, Duration::from_secs(1));
+        let spawned = RemoteTaskId::from_raw(8);
+        harness.inject_spawn(&a, &grantor, spawned);
+        harness.run_for(Duration::from_secs(3));
+
+        let node = harness.node(&grantor).unwrap();
+        assert!(node.is_connected(&a));
+        assert!(node.events().iter().any(|event| matches!(
+            event,
+            NodeEvent::SpawnRejected {
+                task_id,
+                reason: SpawnRejectReason::Unauthorized(reason),
+            } if *task_id == spawned && reason == "spawn requires a certificate"
+        )));
+        assert!(
+            !node
+                .events()
+                .iter()
+                .any(|event| matches!(event, NodeEvent::SpawnReceived { .. }))
+        );
+        let grants = node
+            .events()
+            .iter()
+            .filter(|event| matches!(event, NodeEvent::LeaseGranted { holder, .. } if *holder == a))
+            .count();
+        assert!(grants >= 3, "lease kept renewing, got {grants} grants");
+        assert!(holds(&harness, &a, lease_task));
+    }
+
+    #[test]
+    fn scripted_identities_drive_policy_matrix() {
+        let (mut harness, _, worker) = setup_harness();
+        harness.set_authorizer(&worker, Arc::new(TieredPolicy));
+        let certificate = PeerCredentials::ClientCertificate {
+            subject: "CN=scheduler".into(),
+            fingerprint: [7; 32],
+        };
+        let cases = [
+            ("with-cert", Some(certificate), Some("accepted")),
+            ("with-token", Some(PeerCredentials::Token { id: "ci".into() }), Some("unauthorized")),
+            ("anonymous", None, None),
+        ];
+        let mut spawns = Vec::new();
+        for (name, credentials, expected) in cases {
+            let node = harness.add_node(name);
+            if let Some(credentials) = credentials {
+                harness.set_peer_identity(PeerIdentity::new(node.clone(), credentials));
+            }
+            let task_id = RemoteTaskId::next();
+            harness.inject_spawn(&node, &worker, task_id);
+            spawns.push((node, task_id, expected));
+        }
+        harness.run_for(Duration::from_millis(500));
+
+        let worker = harness.node(&worker).unwrap();
+        for (node, task_id, expected) in &spawns {
+            let outcome = worker.events().iter().find_map(|event| match event {
+                NodeEvent::SpawnAccepted { task_id: id } if id == task_id => Some("accepted"),
+                NodeEvent::SpawnRejected {
+                    task_id: id,
+                    reason: SpawnRejectReason::Unauthorized(_),
+                } if id == task_id => Some("unauthorized"),
+                _ => None,
+            });
+            assert_eq!(outcome, *expected, "spawn from {node}");
+        }
+
+        let mut denials: Vec<_> = worker
+            .authz_audit()
+            .iter()
+            .filter_map(|event| {
+                let reason = event.denied.as_deref()?;
+                Some((event.peer.node.as_str().to_owned(), reason))
+            })
+            .collect();
+        denials.sort_unstable();
+        assert_eq!(
+            denials,
+            vec![
+                ("anonymous".to_owned(), "anonymous member"),
+                ("with-token".to_owned(), "spawn requires a certificate"),
+            ]
+        );
+    }
+
+    fn spawn_with_input(origin: &NodeId, task_id: RemoteTaskId, input: Vec<u8>) -> RemoteMessage {
+        RemoteMessage::SpawnRequest(SpawnRequest {
+            remote_task_id: task_id,
+            computation: crate::remote::ComputationName::new("test-computation"),
+            input: RemoteInput::new(input),
+            lease: Duration::from_secs(30),
+            idempotency_key: IdempotencyKey::from_raw(0xc0de_0000 | u128::from(task_id.raw())),
+            budget: None,
+            origin_node: origin.clone(),
+            origin_region: crate::types::RegionId::new_for_test(0, 0),
+            origin_task: crate::types::TaskId::new_for_test(0, 0),
+        })
+    }
+
+    fn large_payload() -> Vec<u8> {
+        b"remote payload "
+            .iter()
+            .copied()
+            .cycle()
+            .take(32 * 1024)
+            .collect()
+    }
+
+    #[test]
+    fn spawn_remote_payloads_round_trip_between_upgraded_nodes() {
+        let (mut harness, a, b) = setup_harness();
+        let config = CompressionConfig::default().with_threshold(1024);
+        harness.set_compression(&a, config).expect("enable on a");
+        harness.set_compression(&b, config).expect("enable on b");
+        let cap = harness.node(&a).unwrap().create_cap();
+        let cx = Cx::for_testing().with_remote_cap(cap);
+        let spawn = |input: Vec<u8>| {
+            crate::spawn_remote(
+                &cx,
+                b.clone(),
+                crate::ComputationName::new("echo"),
+                RemoteInput::new(input),
+            )
+            .expect("spawn")
+        };
+        let mut large = spawn(large_payload());
+        let mut small = spawn(b"small".to_vec());
+
+        harness.run_for(Duration::from_millis(15));
+        let worker = harness.node(&b).unwrap();
+        let running = worker
+            .running_task(large.remote_task_id())
+            .expect("large spawn accepted");
+        assert_eq!(running.input.data(), large_payload().as_slice());
+
+        harness.run_for(Duration::from_millis(200));
+        for (handle, expected) in [(&mut large, large_payload()), (&mut small, b"small".to_vec())] {
+            let outcome = handle
+                .try_join()
+                .expect("result available")
+                .expect("remote outcome");
+            assert!(matches!(outcome, RemoteOutcome::Success(ref data) if *data == expected));
+        }
+
+        let lz4 = CompressionAlgorithm::Lz4.is_available();
+        let origin = harness.node(&a).unwrap().compression().unwrap();
+        assert_eq!(origin.negotiated(&b), lz4.then_some(CompressionAlgorithm::Lz4));
+        let expected = u64::from(lz4);
+        let sent = origin.compressor().metrics();
+        assert_eq!(sent.compressed, expected, "large input compressed by a");
+        assert_eq!(sent.passthrough, expected, "small input sent as-is by a");
+        assert_eq!(sent.decompressed, expected, "large result inflated by a");
+        let worker = harness.node(&b).unwrap().compression().unwrap();
+        let received = worker.compressor().metrics();
+        assert_eq!(received.decompressed, expected, "large input inflated by b");
+        assert_eq!(received.compressed, expected, "large result compressed by b");
+        assert_eq!(received.passthrough, expected, "small result sent as-is by b");
+    }
+
+    #[test]
+    fn legacy_peer_receives_uncompressed_payloads() {
+        let (mut harness, a, b) = setup_harness();
+        let config = CompressionConfig::default().with_threshold(1024);
+        harness.set_compression(&a, config).expect("enable on a");
+        let task_id = RemoteTaskId::from_raw(9102);
+        let mut rx = register_pending_result(harness.nodes.get_mut(&a).unwrap(), task_id);
+
+        harness.send_message(&a, &b, &spawn_with_input(&a, task_id, large_payload()));
+        harness.run_for(Duration::from_millis(200));
+        let outcome = rx.try_recv().expect("result delivered");
+        assert!(
+            matches!(outcome, Ok(RemoteOutcome::Success(ref data)) if *data == large_payload())
+        );
+
+        let origin = harness.node(&a).unwrap().compression().unwrap();
+        assert_eq!(origin.negotiated(&b), None);
+        assert_eq!(origin.compressor().metrics().payloads, 0);
+    }
+
+    #[test]
+    fn undecodable_payload_is_dropped_and_logged() {
+        let (mut harness, a, b) = setup_harness();
+        let config = CompressionConfig::default().with_threshold(1024);
+        harness.set_compression(&a, config).expect("enable on a");
+        let bounded = config.with_max_decompressed_len(4096);
+        harness.set_compression(&b, bounded).expect("enable on b");
+        let task_id = RemoteTaskId::from_raw(9103);
+
+        // Above b's decompression bound, so b must refuse to inflate it.
+        harness.send_message(&a, &b, &spawn_with_input(&a, task_id, vec![7; 8192]));
+        harness.run_for(Duration::from_millis(10));
+
+        let worker = harness.node(&b).unwrap();
+        if CompressionAlgorithm::Lz4.is_available() {
+            assert!(worker.running_task(task_id).is_none());
+            assert!(worker.events().iter().any(|event| matches!(
+                event,
+                NodeEvent::PayloadRejected {
+                    error: CompressionError::TooLarge { .. },
+                    ..
+                }
+            )));
+        } else {
+            assert!(worker.running_task(task_id).is_some());
+        }
+    }
 }
@@ -1,15 +1,28 @@
 //! Deterministic network simulation for distributed testing.
 
 mod config;
+mod connect;
 pub mod harness;
+mod multicast;
 mod network;
+mod topology;
 
 pub use config::{JitterModel, LatencyModel, NetworkConditions, NetworkConfig};
+pub use connect::{
+    ConnectFaults, ConnectSim, ConnectSimConfig, ConnectTraceEvent, ConnectTraceKind,
+    HandshakeFault, HandshakeOutcome, ResolveFault, ResolveOutcome, SimResolver, SimTls,
+    SimUpstream,
+};
 pub use harness::{
-    DistributedHarness, FaultScript, HarnessFault, HarnessTraceEvent, HarnessTraceKind, NodeEvent,
-    SimNode,
+    ClockOffset, DistributedHarness, FaultScript, HarnessFault, HarnessTraceEvent,
+    HarnessTraceKind, NodeEvent, SimNode,
 };
+pub use multicast::SimMulticastDomain;
 pub use network::{
     DeterministicNetwork, Fault, HostId, NetworkMetrics, NetworkTraceEvent, NetworkTraceKind,
     Packet,
 };
+pub use topology::{
+    LinkProfile, Topology, TopologyBuilder, TopologyDropReason, TopologyError, TopologyMutation,
+    TopologyNetwork, TopologyPacket, TopologyTraceEvent, TopologyTraceKind,
+};
@@ -0,0 +1,185 @@
+//! Simulated multicast domain on top of the deterministic network.
+
+use super::config::NetworkConfig;
+use super::network::{DeterministicNetwork, Fault, HostId, Packet};
+use crate::bytes::Bytes;
+use crate::types::Time;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// A single multicast group over a [`DeterministicNetwork`].
+///
+/// A datagram sent to the group is fanned out as one unicast packet per
+/// member, so every copy is subject to the sender's link conditions, faults
+/// and partitions independently. Members are visited in host-id order, which
+/// keeps delivery order reproducible for a given network seed.
+#[derive(Debug)]
+pub struct SimMulticastDomain {
+    network: DeterministicNetwork,
+    members: BTreeSet<HostId>,
+    loopback: bool,
+}
+
+impl SimMulticastDomain {
+    /// Creates an empty domain over a fresh network.
+    #[must_use]
+    pub fn new(config: NetworkConfig) -> Self {
+        Self {
+            network: DeterministicNetwork::new(config),
+            members: BTreeSet::new(),
+            loopback: false,
+        }
+    }
+
+    /// Delivers group datagrams back to their sender as well, like
+    /// `IP_MULTICAST_LOOP`. Disabled by default.
+    #[must_use]
+    pub fn with_loopback(mut self, loopback: bool) -> Self {
+        self.loopback = loopback;
+        self
+    }
+
+    /// Adds a host and joins it to the group.
+    pub fn add_host(&mut self, name: impl Into<String>) -> HostId {
+        let host = self.network.add_host(name);
+        self.members.insert(host);
+        host
+    }
+
+    /// Joins `host` to the group. Returns `false` if it was already a member.
+    pub fn join(&mut self, host: HostId) -> bool {
+        self.members.insert(host)
+    }
+
+    /// Removes `host` from the group. Packets already in flight still arrive.
+    pub fn leave(&mut self, host: HostId) -> bool {
+        self.members.remove(&host)
+    }
+
+    /// Returns the current members in host-id order.
+    #[must_use]
+    pub fn members(&self) -> Vec<HostId> {
+        self.members.iter().copied().collect()
+    }
+
+    /// Sends `payload` from `src` to every member of the group.
+    ///
+    /// The sender does not need to be a member, matching IP multicast.
+    pub fn send(&mut self, src: HostId, payload: &Bytes) {
+        let targets: Vec<HostId> = self
+            .members
+            .iter()
+            .copied()
+            .filter(|&member| self.loopback || member != src)
+            .collect();
+        for dst in targets {
+            self.network.send(src, dst, payload.clone());
+        }
+    }
+
+    /// Sends `payload` from `src` to a single host, like a unicast reply.
+    pub fn send_to(&mut self, src: HostId, dst: HostId, payload: Bytes) {
+        self.network.send(src, dst, payload);
+    }
+
+    /// Drains the packets delivered to `host`.
+    pub fn take_inbox(&mut self, host: HostId) -> Vec<Packet> {
+        self.network.take_inbox(host).unwrap_or_default()
+    }
+
+    /// Returns the current virtual time.
+    #[must_use]
+    pub const fn now(&self) -> Time {
+        self.network.now()
+    }
+
+    /// Delivers every packet due by `target` and advances the clock to it.
+    pub fn run_until(&mut self, target: Time) {
+        self.network.run_until(target);
+    }
+
+    /// Advances the clock by `duration`, delivering due packets.
+    pub fn run_for(&mut self, duration: Duration) {
+        self.network.run_for(duration);
+    }
+
+    /// Injects a fault into the underlying network.
+    pub fn inject_fault(&mut self, fault: &Fault) {
+        self.network.inject_fault(fault);
+    }
+
+    /// Returns the underlying network.
+    #[must_use]
+    pub const fn network(&self) -> &DeterministicNetwork {
+        &self.network
+    }
+
+    /// Returns the underlying network mutably, e.g. to set link conditions.
+    pub fn network_mut(&mut self) -> &mut DeterministicNetwork {
+        &mut self.network
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::network::NetworkConditions;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn ideal_domain() -> SimMulticastDomain {
+        let config = NetworkConfig {
+            default_conditions: NetworkConditions::ideal(),
+            ..NetworkConfig::default()
+        };
+        SimMulticastDomain::new(config)
+    }
+
+    #[test]
+    fn group_send_reaches_every_other_member() {
+        init_test("group_send_reaches_every_other_member");
+        let mut domain = ideal_domain();
+        let a = domain.add_host("a");
+        let b = domain.add_host("b");
+        let c = domain.add_host("c");
+        assert!(domain.leave(c));
+
+        domain.send(a, &Bytes::from_static(b"hello"));
+        domain.run_for(Duration::from_millis(1));
+
+        assert!(domain.take_inbox(a).is_empty(), "no loopback by default");
+        let inbox = domain.take_inbox(b);
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].src, a);
+        assert_eq!(&inbox[0].payload[..], b"hello");
+        assert!(domain.take_inbox(c).is_empty(), "left members receive nothing");
+        crate::test_complete!("group_send_reaches_every_other_member");
+    }
+
+    #[test]
+    fn loopback_and_crash_faults() {
+        init_test("loopback_and_crash_faults");
+        let mut domain = ideal_domain().with_loopback(true);
+        let a = domain.add_host("a");
+        let b = domain.add_host("b");
+        domain.inject_fault(&Fault::HostCrash { host: b });
+
+        domain.send(a, &Bytes::from_static(b"ping"));
+        domain.run_for(Duration::from_millis(1));
+
+        assert_eq!(domain.take_inbox(a).len(), 1);
+        assert!(domain.take_inbox(b).is_empty());
+        crate::test_complete!("loopback_and_crash_faults");
+    }
+}
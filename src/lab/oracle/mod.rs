@@ -18,6 +18,8 @@
 //! - [`FinalizerOracle`] verifies all registered finalizers ran.
 //! - [`RegionTreeOracle`] verifies INV-TREE: regions form a proper rooted tree.
 //! - [`DeadlineMonotoneOracle`] verifies INV-DEADLINE-MONOTONE: child deadlines ≤ parent deadlines.
+//! - [`OrderingOracle`] checks user-declared happens-before, mutual-exclusion, and
+//!   occurrence rules over `Cx::mark` markers.
 //!
 //! # Actor-Specific Oracles
 //!
@@ -50,6 +52,7 @@ pub mod fabric;
 pub mod finalizer;
 pub mod loser_drain;
 pub mod obligation_leak;
+pub mod ordering;
 pub mod priority_inversion;
 pub mod quiescence;
 pub mod region_leak;
@@ -104,6 +107,9 @@ pub use fabric::{
 pub use finalizer::{FinalizerId, FinalizerOracle, FinalizerViolation};
 pub use loser_drain::{LoserDrainOracle, LoserDrainViolation};
 pub use obligation_leak::{ObligationLeakOracle, ObligationLeakViolation};
+pub use ordering::{
+    Marker, MarkerWitness, OrderingOracle, OrderingRule, OrderingViolation, OrderingViolationKind,
+};
 pub use priority_inversion::{
     InversionId, InversionType, Priority, PriorityInversion, PriorityInversionConfig,
     PriorityInversionOracle, PriorityInversionStatistics, ResourceId,
@@ -0,0 +1,970 @@
+//! User-defined ordering oracles over marker events.
+//!
+//! Application code records named markers with structured fields through
+//! [`Cx::mark`](crate::cx::Cx::mark):
+//!
+//! ```ignore
+//! cx.mark("db_commit", &[("key", &key)]);
+//! // ...
+//! cx.mark("cache_invalidate", &[("key", &key)]);
+//! ```
+//!
+//! An [`OrderingOracle`] then checks declared invariants over the markers
+//! found in a recorded trace:
+//!
+//! | Rule | Holds when |
+//! |------|------------|
+//! | [`OrderingRule::HappensBefore`] | every `after` marker is preceded by a `before` marker with the same key |
+//! | [`OrderingRule::MutualExclusion`] | no two tasks are between `enter` and `exit` for the same key at once |
+//! | [`OrderingRule::AtMostOnce`] | a marker occurs at most once per key |
+//! | [`OrderingRule::ExactlyOnce`] | a marker occurs exactly once per key (and for every expected key) |
+//!
+//! Rules are keyed by naming a payload field with [`OrderingRule::keyed_by`];
+//! markers lacking that field do not participate in the rule.
+//!
+//! # Ordering model
+//!
+//! The lab runtime polls one task at a time, so the recorded marker order is
+//! a linearization of the run's happens-before relation: a marker that
+//! causally precedes another is recorded first under every schedule. Rules
+//! only compare matched markers with each other and never look at absolute
+//! step indices, so reordering unrelated work does not change the verdict.
+//! Program order within a task is the causal path a violation report shows
+//! for each marker involved.
+//!
+//! Rules deserialize from scenario files (see the `ordering` section of
+//! [`Scenario`](crate::lab::scenario::Scenario)):
+//!
+//! ```yaml
+//! ordering:
+//!   - rule: happens_before
+//!     before: db_commit
+//!     after: cache_invalidate
+//!     key: key
+//!   - rule: mutual_exclusion
+//!     enter: flush_begin
+//!     exit: flush_end
+//! ```
+//!
+//! # Usage
+//!
+//! ```ignore
+//! let oracle = OrderingOracle::new()
+//!     .with_rule(OrderingRule::happens_before("db_commit", "cache_invalidate").keyed_by("key"));
+//! runtime.run_until_quiescent();
+//! oracle.check(&runtime.trace().snapshot())?;
+//! ```
+
+use crate::trace::{TraceData, TraceEvent, TraceEventKind};
+use crate::types::{TaskId, Time};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Prefix identifying marker payloads among user trace messages.
+const MARKER_PREFIX: &str = "marker ";
+
+/// Number of same-task markers shown on a violation's causal path.
+const CAUSAL_PATH_LEN: usize = 8;
+
+#[derive(Serialize, Deserialize)]
+struct MarkerPayload {
+    name: String,
+    task: String,
+    fields: BTreeMap<String, String>,
+}
+
+/// Encodes a marker as the message of a user trace event.
+pub(crate) fn encode_marker(name: &str, task: TaskId, fields: &[(&str, &str)]) -> String {
+    let payload = MarkerPayload {
+        name: name.to_owned(),
+        task: format!("{task:#}"),
+        fields: fields
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect(),
+    };
+    let json = serde_json::to_string(&payload).unwrap_or_default();
+    format!("{MARKER_PREFIX}{json}")
+}
+
+/// A named marker recorded by [`Cx::mark`](crate::cx::Cx::mark).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    /// Trace sequence number of the marker event.
+    pub seq: u64,
+    /// Time at which the marker was recorded.
+    pub time: Time,
+    /// Marker name.
+    pub name: String,
+    /// Task that recorded the marker.
+    pub task: TaskId,
+    /// Structured payload fields.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Marker {
+    /// Decodes a marker from a trace event, if the event carries one.
+    #[must_use]
+    pub fn from_event(event: &TraceEvent) -> Option<Self> {
+        let (TraceEventKind::UserTrace, TraceData::Message(message)) = (&event.kind, &event.data)
+        else {
+            return None;
+        };
+        let json = message.strip_prefix(MARKER_PREFIX)?;
+        let payload: MarkerPayload = serde_json::from_str(json).ok()?;
+        Some(Self {
+            seq: event.seq,
+            time: event.time,
+            name: payload.name,
+            task: payload.task.parse().ok()?,
+            fields: payload.fields,
+        })
+    }
+
+    /// Returns the value of a payload field.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+impl fmt::Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.fields.is_empty() {
+            f.write_str("{")?;
+            for (index, (key, value)) in self.fields.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{key}={value}")?;
+            }
+            f.write_str("}")?;
+        }
+        write!(f, " (seq {}, {})", self.seq, self.task)
+    }
+}
+
+/// Extracts every marker from a trace, in trace order.
+#[must_use]
+pub fn markers(trace: &[TraceEvent]) -> Vec<Marker> {
+    trace.iter().filter_map(Marker::from_event).collect()
+}
+
+/// An ordering invariant over marker names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum OrderingRule {
+    /// Every `after` marker must be preceded by a `before` marker.
+    HappensBefore {
+        /// Marker that must come first.
+        before: String,
+        /// Marker that must come second.
+        after: String,
+        /// Payload field both markers must agree on.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+    /// Sections delimited by `enter` and `exit` must not overlap across
+    /// tasks.
+    MutualExclusion {
+        /// Marker opening the section.
+        enter: String,
+        /// Marker closing the section.
+        exit: String,
+        /// Payload field naming the protected resource.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+    /// A marker occurs at most once.
+    AtMostOnce {
+        /// Marker name.
+        marker: String,
+        /// Payload field the bound applies per value of.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+    /// A marker occurs exactly once.
+    ExactlyOnce {
+        /// Marker name.
+        marker: String,
+        /// Payload field the bound applies per value of.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        /// Key values that must each occur; unkeyed rules require one
+        /// occurrence overall.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        expected_keys: Vec<String>,
+    },
+}
+
+impl OrderingRule {
+    /// Requires every `after` marker to be preceded by a `before` marker.
+    #[must_use]
+    pub fn happens_before(before: impl Into<String>, after: impl Into<String>) -> Self {
+        Self::HappensBefore {
+            before: before.into(),
+            after: after.into(),
+            key: None,
+        }
+    }
+
+    /// Forbids two tasks from being inside `enter`..`exit` at once.
+    #[must_use]
+    pub fn mutual_exclusion(enter: impl Into<String>, exit: impl Into<String>) -> Self {
+        Self::MutualExclusion {
+            enter: enter.into(),
+            exit: exit.into(),
+            key: None,
+        }
+    }
+
+    /// Allows `marker` to occur at most once.
+    #[must_use]
+    pub fn at_most_once(marker: impl Into<String>) -> Self {
+        Self::AtMostOnce {
+            marker: marker.into(),
+            key: None,
+        }
+    }
+
+    /// Requires `marker` to occur exactly once.
+    #[must_use]
+    pub fn exactly_once(marker: impl Into<String>) -> Self {
+        Self::ExactlyOnce {
+            marker: marker.into(),
+            key: None,
+            expected_keys: Vec::new(),
+        }
+    }
+
+    /// Scopes the rule to markers agreeing on payload field `field`.
+    #[must_use]
+    pub fn keyed_by(mut self, field: impl Into<String>) -> Self {
+        match &mut self {
+            Self::HappensBefore { key, .. }
+            | Self::MutualExclusion { key, .. }
+            | Self::AtMostOnce { key, .. }
+            | Self::ExactlyOnce { key, .. } => *key = Some(field.into()),
+        }
+        self
+    }
+
+    /// Lists key values an [`ExactlyOnce`](Self::ExactlyOnce) rule expects
+    /// to see; other rules ignore it.
+    #[must_use]
+    pub fn expecting_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if let Self::ExactlyOnce { expected_keys, .. } = &mut self {
+            expected_keys.extend(keys.into_iter().map(Into::into));
+        }
+        self
+    }
+
+    /// Returns the payload field the rule is keyed by.
+    #[must_use]
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::HappensBefore { key, .. }
+            | Self::MutualExclusion { key, .. }
+            | Self::AtMostOnce { key, .. }
+            | Self::ExactlyOnce { key, .. } => key.as_deref(),
+        }
+    }
+
+    /// Describes why the rule cannot be evaluated, if it is malformed.
+    #[must_use]
+    pub fn validate(&self) -> Option<&'static str> {
+        let names = match self {
+            Self::HappensBefore { before, after, .. } => {
+                if before == after {
+                    return Some("happens_before needs two distinct markers");
+                }
+                vec![before, after]
+            }
+            Self::MutualExclusion { enter, exit, .. } => {
+                if enter == exit {
+                    return Some("mutual_exclusion needs distinct enter and exit markers");
+                }
+                vec![enter, exit]
+            }
+            Self::AtMostOnce { marker, .. } | Self::ExactlyOnce { marker, .. } => vec![marker],
+        };
+        if names.iter().any(|name| name.is_empty()) {
+            return Some("marker names must not be empty");
+        }
+        if self.key() == Some("") {
+            return Some("key field must not be empty");
+        }
+        None
+    }
+}
+
+impl fmt::Display for OrderingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HappensBefore { before, after, .. } => {
+                write!(f, "happens_before({before} -> {after}")?;
+            }
+            Self::MutualExclusion { enter, exit, .. } => {
+                write!(f, "mutual_exclusion({enter} .. {exit}")?;
+            }
+            Self::AtMostOnce { marker, .. } => write!(f, "at_most_once({marker}")?,
+            Self::ExactlyOnce { marker, .. } => write!(f, "exactly_once({marker}")?,
+        }
+        if let Some(key) = self.key() {
+            write!(f, " by {key}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// How an [`OrderingRule`] was broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderingViolationKind {
+    /// An `after` marker had no earlier `before` marker.
+    MissingPredecessor,
+    /// A task entered a section another task had not yet exited.
+    Overlap,
+    /// A marker occurred again.
+    Repeated,
+    /// A required marker never occurred.
+    Missing,
+}
+
+/// A marker involved in a violation, with the program-order path that led to
+/// it on its task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerWitness {
+    /// The marker itself.
+    pub marker: Marker,
+    /// Up to eight markers of the same task, oldest first, ending with
+    /// `marker`.
+    pub causal_path: Vec<Marker>,
+}
+
+impl fmt::Display for MarkerWitness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "path on {}: ", self.marker.task)?;
+        for (index, step) in self.causal_path.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" -> ")?;
+            }
+            write!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A broken ordering rule with the concrete markers involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderingViolation {
+    /// The rule that was broken.
+    pub rule: OrderingRule,
+    /// How it was broken.
+    pub kind: OrderingViolationKind,
+    /// Key value the violation is scoped to, for keyed rules.
+    pub key: Option<String>,
+    /// The marker that broke the rule: the unpreceded `after`, the second
+    /// `enter`, or the repeated occurrence. `None` for
+    /// [`Missing`](OrderingViolationKind::Missing).
+    pub offending: Option<MarkerWitness>,
+    /// The marker it conflicts with: the late `before`, the `enter` still
+    /// holding the section, or the first occurrence.
+    pub related: Option<MarkerWitness>,
+}
+
+impl fmt::Display for OrderingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ordering rule {} violated", self.rule)?;
+        if let Some(key) = &self.key {
+            write!(f, " for key {key:?}")?;
+        }
+        f.write_str(": ")?;
+        let offending = self.offending.as_ref().map(|witness| &witness.marker);
+        let related = self.related.as_ref().map(|witness| &witness.marker);
+        match (&self.rule, self.kind, offending) {
+            (OrderingRule::HappensBefore { before, .. }, _, Some(offending)) => {
+                write!(f, "{offending} has no earlier {before}")?;
+                match related {
+                    Some(related) => write!(f, "; the first {related} came after it")?,
+                    None => write!(f, "; no {before} was recorded")?,
+                }
+            }
+            (_, OrderingViolationKind::Overlap, Some(offending)) => {
+                write!(f, "{offending} entered")?;
+                if let Some(related) = related {
+                    write!(f, " while {related} had not exited")?;
+                }
+            }
+            (_, OrderingViolationKind::Repeated, Some(offending)) => {
+                write!(f, "{offending} repeats")?;
+                if let Some(related) = related {
+                    write!(f, " {related}")?;
+                }
+            }
+            _ => f.write_str("no matching marker was recorded")?,
+        }
+        for witness in [&self.offending, &self.related].into_iter().flatten() {
+            write!(f, "\n  {witness}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for OrderingViolation {}
+
+/// Checks [`OrderingRule`]s against the markers of a recorded trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderingOracle {
+    rules: Vec<OrderingRule>,
+}
+
+impl OrderingOracle {
+    /// Creates an oracle with no rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an oracle enforcing `rules`.
+    #[must_use]
+    pub fn from_rules(rules: impl IntoIterator<Item = OrderingRule>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    /// Adds a rule.
+    #[must_use]
+    pub fn with_rule(mut self, rule: OrderingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns the rules this oracle enforces.
+    #[must_use]
+    pub fn rules(&self) -> &[OrderingRule] {
+        &self.rules
+    }
+
+    /// Checks every rule against the markers in `trace`.
+    pub fn check(&self, trace: &[TraceEvent]) -> Result<(), Vec<OrderingViolation>> {
+        self.check_markers(&markers(trace))
+    }
+
+    /// Checks every rule against markers already extracted in trace order.
+    ///
+    /// Runs in `O(rules * markers * log keys)`; each broken rule reports at
+    /// most one violation per key.
+    pub fn check_markers(&self, markers: &[Marker]) -> Result<(), Vec<OrderingViolation>> {
+        let lanes = TaskLanes::new(markers);
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            let mut check = RuleCheck {
+                rule,
+                markers,
+                lanes: &lanes,
+                violations: &mut violations,
+            };
+            match rule {
+                OrderingRule::HappensBefore { before, after, .. } => {
+                    check.happens_before(before, after);
+                }
+                OrderingRule::MutualExclusion { enter, exit, .. } => {
+                    check.mutual_exclusion(enter, exit);
+                }
+                OrderingRule::AtMostOnce { marker, .. } => check.occurrences(marker, &[], false),
+                OrderingRule::ExactlyOnce {
+                    marker,
+                    expected_keys,
+                    ..
+                } => check.occurrences(marker, expected_keys, true),
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+/// Per-task program order over the marker list.
+struct TaskLanes {
+    lanes: BTreeMap<TaskId, Vec<usize>>,
+    position: Vec<usize>,
+}
+
+impl TaskLanes {
+    fn new(markers: &[Marker]) -> Self {
+        let mut lanes: BTreeMap<TaskId, Vec<usize>> = BTreeMap::new();
+        let mut position = Vec::with_capacity(markers.len());
+        for (index, marker) in markers.iter().enumerate() {
+            let lane = lanes.entry(marker.task).or_default();
+            position.push(lane.len());
+            lane.push(index);
+        }
+        Self { lanes, position }
+    }
+
+    fn witness(&self, markers: &[Marker], index: usize) -> MarkerWitness {
+        let marker = &markers[index];
+        let end = self.position[index] + 1;
+        let start = end.saturating_sub(CAUSAL_PATH_LEN);
+        let causal_path = self.lanes[&marker.task][start..end]
+            .iter()
+            .map(|&step| markers[step].clone())
+            .collect();
+        MarkerWitness {
+            marker: marker.clone(),
+            causal_path,
+        }
+    }
+}
+
+struct RuleCheck<'a> {
+    rule: &'a OrderingRule,
+    markers: &'a [Marker],
+    lanes: &'a TaskLanes,
+    violations: &'a mut Vec<OrderingViolation>,
+}
+
+impl<'a> RuleCheck<'a> {
+    /// Yields `(index, key)` for markers named `name` that carry the rule's
+    /// key field.
+    fn matching(&self, name: &'a str) -> impl Iterator<Item = (usize, Option<&'a str>)> + 'a {
+        let key_field = self.rule.key();
+        let markers: &'a [Marker] = self.markers;
+        markers
+            .iter()
+            .enumerate()
+            .filter(move |(_, marker)| marker.name == name)
+            .filter_map(move |(index, marker)| match key_field {
+                None => Some((index, None)),
+                Some(field) => marker.field(field).map(|key| (index, Some(key))),
+            })
+    }
+
+    fn report(
+        &mut self,
+        kind: OrderingViolationKind,
+        key: Option<&str>,
+        offending: Option<usize>,
+        related: Option<usize>,
+    ) {
+        let witness = |index| self.lanes.witness(self.markers, index);
+        let violation = OrderingViolation {
+            rule: self.rule.clone(),
+            kind,
+            key: key.map(str::to_owned),
+            offending: offending.map(witness),
+            related: related.map(witness),
+        };
+        self.violations.push(violation);
+    }
+
+    fn happens_before(&mut self, before: &'a str, after: &'a str) {
+        let mut first_before: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+        for (index, key) in self.matching(before) {
+            first_before.entry(key).or_insert(index);
+        }
+        let mut reported = BTreeSet::new();
+        let afters: Vec<_> = self.matching(after).collect();
+        for (index, key) in afters {
+            let earliest = first_before.get(&key).copied();
+            if earliest.is_some_and(|earliest| earliest < index) || !reported.insert(key) {
+                continue;
+            }
+            self.report(
+                OrderingViolationKind::MissingPredecessor,
+                key,
+                Some(index),
+                earliest,
+            );
+        }
+    }
+
+    fn mutual_exclusion(&mut self, enter: &'a str, exit: &'a str) {
+        // Holder per key: (task, entering marker, nesting depth).
+        let mut holders: BTreeMap<Option<&str>, (TaskId, usize, usize)> = BTreeMap::new();
+        let mut reported = BTreeSet::new();
+        let mut events: Vec<_> = self
+            .matching(enter)
+            .map(|(index, key)| (index, key, true))
+            .chain(self.matching(exit).map(|(index, key)| (index, key, false)))
+            .collect();
+        events.sort_unstable_by_key(|&(index, _, _)| index);
+
+        for (index, key, entering) in events {
+            let task = self.markers[index].task;
+            match holders.get_mut(&key) {
+                Some((holder, _, depth)) if *holder == task => {
+                    if entering {
+                        *depth += 1;
+                    } else {
+                        *depth -= 1;
+                        if *depth == 0 {
+                            holders.remove(&key);
+                        }
+                    }
+                }
+                Some(&mut (_, held_by, _)) => {
+                    if entering && reported.insert((key, held_by)) {
+                        self.report(
+                            OrderingViolationKind::Overlap,
+                            key,
+                            Some(index),
+                            Some(held_by),
+                        );
+                    }
+                }
+                None => {
+                    if entering {
+                        holders.insert(key, (task, index, 1));
+                    }
+                }
+            }
+        }
+    }
+
+    fn occurrences(&mut self, marker: &'a str, expected_keys: &'a [String], exactly: bool) {
+        let mut first: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+        let mut reported = BTreeSet::new();
+        let occurrences: Vec<_> = self.matching(marker).collect();
+        for (index, key) in occurrences {
+            match first.get(&key) {
+                Some(&earlier) => {
+                    if reported.insert(key) {
+                        self.report(
+                            OrderingViolationKind::Repeated,
+                            key,
+                            Some(index),
+                            Some(earlier),
+                        );
+                    }
+                }
+                None => {
+                    first.insert(key, index);
+                }
+            }
+        }
+        if !exactly {
+            return;
+        }
+        if self.rule.key().is_none() {
+            if first.is_empty() {
+                self.report(OrderingViolationKind::Missing, None, None, None);
+            }
+            return;
+        }
+        for key in expected_keys {
+            if !first.contains_key(&Some(key.as_str())) {
+                self.report(OrderingViolationKind::Missing, Some(key), None, None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::mpsc;
+    use crate::cx::Cx;
+    use crate::lab::LabRuntime;
+    use crate::runtime::yield_now;
+    use crate::sync::Mutex;
+    use crate::types::Budget;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    type LabTask = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn run_lab(seed: u64, tasks: Vec<LabTask>) -> Vec<TraceEvent> {
+        let mut runtime = LabRuntime::with_seed(seed);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        for future in tasks {
+            let (task, _handle) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, future)
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task, 0);
+        }
+        runtime.run_until_quiescent();
+        runtime.trace().snapshot()
+    }
+
+    /// A writer commits keys and hands them to an invalidator over a
+    /// channel; `commit_late` moves the commit marker after the handoff.
+    fn commit_then_invalidate(commit_late: bool) -> Vec<LabTask> {
+        let (tx, mut rx) = mpsc::channel::<u64>(4);
+        let writer = async move {
+            let cx = Cx::current().expect("task cx");
+            for key in 0..4_u64 {
+                let key_str = key.to_string();
+                if !commit_late {
+                    cx.mark("db_commit", &[("key", &key_str)]);
+                }
+                tx.send(&cx, key).await.expect("send");
+                if commit_late {
+                    yield_now().await;
+                    cx.mark("db_commit", &[("key", &key_str)]);
+                }
+            }
+        };
+        let invalidator = async move {
+            let cx = Cx::current().expect("task cx");
+            while let Ok(key) = rx.recv(&cx).await {
+                cx.mark("cache_invalidate", &[("key", &key.to_string())]);
+            }
+        };
+        vec![Box::pin(writer), Box::pin(invalidator)]
+    }
+
+    fn critical_sections(locked: bool) -> Vec<LabTask> {
+        let mutex = Arc::new(Mutex::new(()));
+        (0..3)
+            .map(|_| -> LabTask {
+                let mutex = Arc::clone(&mutex);
+                Box::pin(async move {
+                    let cx = Cx::current().expect("task cx");
+                    for _ in 0..3 {
+                        let guard = if locked {
+                            Some(mutex.lock(&cx).await.expect("lock"))
+                        } else {
+                            None
+                        };
+                        cx.mark("flush_begin", &[("table", "orders")]);
+                        yield_now().await;
+                        cx.mark("flush_end", &[("table", "orders")]);
+                        drop(guard);
+                        yield_now().await;
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn commit_before_invalidate() -> OrderingRule {
+        OrderingRule::happens_before("db_commit", "cache_invalidate")
+            .keyed_by("key")
+    }
+
+    fn flush_exclusion() -> OrderingRule {
+        OrderingRule::mutual_exclusion("flush_begin", "flush_end")
+            .keyed_by("table")
+    }
+
+    fn marker_event(seq: u64, task: u32, name: &str, fields: &[(&str, &str)]) -> TraceEvent {
+        let task = TaskId::new_for_test(task, 0);
+        TraceEvent::user_trace(seq, Time::ZERO, encode_marker(name, task, fields))
+    }
+
+    #[test]
+    fn correct_handoff_passes_across_seeds() {
+        init_test("correct_handoff_passes_across_seeds");
+        let oracle = OrderingOracle::new()
+            .with_rule(commit_before_invalidate())
+            .with_rule(OrderingRule::exactly_once("cache_invalidate").keyed_by("key"));
+        for seed in 0..32 {
+            let trace = run_lab(seed, commit_then_invalidate(false));
+            let count = markers(&trace).len();
+            crate::assert_with_log!(count == 8, "markers recorded", 8, count);
+            let verdict = oracle.check(&trace);
+            crate::assert_with_log!(verdict.is_ok(), "seed passes", "Ok", verdict);
+        }
+        crate::test_complete!("correct_handoff_passes_across_seeds");
+    }
+
+    #[test]
+    fn injected_late_commit_is_reported_with_paths() {
+        init_test("injected_late_commit_is_reported_with_paths");
+        let oracle = OrderingOracle::new().with_rule(commit_before_invalidate());
+        let violations = (0..16)
+            .find_map(|seed| {
+                let trace = run_lab(seed, commit_then_invalidate(true));
+                oracle.check(&trace).err()
+            })
+            .expect("late commit is caught under some seed");
+        let violation = &violations[0];
+        crate::assert_with_log!(
+            violation.kind == OrderingViolationKind::MissingPredecessor,
+            "kind",
+            OrderingViolationKind::MissingPredecessor,
+            violation.kind
+        );
+        let offending = violation.offending.as_ref().expect("offending marker");
+        let related = violation.related.as_ref().expect("late commit");
+        crate::assert_with_log!(
+            offending.marker.name == "cache_invalidate" && related.marker.name == "db_commit",
+            "witnesses",
+            "cache_invalidate / db_commit",
+            (&offending.marker.name, &related.marker.name)
+        );
+        let ordered = related.marker.seq > offending.marker.seq;
+        crate::assert_with_log!(ordered, "commit came after", true, ordered);
+        let report = violation.to_string();
+        let actionable = report.contains("has no earlier db_commit")
+            && report.contains("came after it")
+            && report.contains("path on");
+        crate::assert_with_log!(actionable, "report", "names both events", report);
+        crate::test_complete!("injected_late_commit_is_reported_with_paths");
+    }
+
+    #[test]
+    fn keyed_rules_only_match_equal_keys() {
+        init_test("keyed_rules_only_match_equal_keys");
+        let trace = vec![
+            marker_event(1, 1, "db_commit", &[("key", "a")]),
+            marker_event(2, 2, "cache_invalidate", &[("key", "b")]),
+            marker_event(3, 2, "cache_invalidate", &[("key", "a")]),
+            marker_event(4, 2, "cache_invalidate", &[]),
+        ];
+        let keyed = OrderingOracle::new().with_rule(commit_before_invalidate());
+        let violations = keyed.check(&trace).expect_err("key b has no commit");
+        let keys: Vec<_> = violations.iter().map(|v| v.key.as_deref()).collect();
+        crate::assert_with_log!(keys == [Some("b")], "violating keys", [Some("b")], keys);
+        let related = violations[0].related.is_none();
+        crate::assert_with_log!(related, "no commit for b at all", true, related);
+
+        let unkeyed = OrderingOracle::new()
+            .with_rule(OrderingRule::happens_before("db_commit", "cache_invalidate"));
+        let verdict = unkeyed.check(&trace);
+        crate::assert_with_log!(verdict.is_ok(), "unkeyed passes", "Ok", verdict);
+        crate::test_complete!("keyed_rules_only_match_equal_keys");
+    }
+
+    #[test]
+    fn mutual_exclusion_holds_under_lock_and_catches_unlocked_overlap() {
+        init_test("mutual_exclusion_holds_under_lock_and_catches_unlocked_overlap");
+        let oracle = OrderingOracle::new().with_rule(flush_exclusion());
+        for seed in 0..16 {
+            let verdict = oracle.check(&run_lab(seed, critical_sections(true)));
+            crate::assert_with_log!(verdict.is_ok(), "locked sections", "Ok", verdict);
+        }
+        let violations = (0..16)
+            .find_map(|seed| {
+                let trace = run_lab(seed, critical_sections(false));
+                oracle.check(&trace).err()
+            })
+            .expect("unlocked sections overlap under some seed");
+        let violation = &violations[0];
+        let offending = violation.offending.as_ref().expect("second enter");
+        let holder = violation.related.as_ref().expect("holder");
+        let distinct = offending.marker.task != holder.marker.task;
+        crate::assert_with_log!(
+            violation.kind == OrderingViolationKind::Overlap && distinct,
+            "overlap across tasks",
+            true,
+            violation
+        );
+        crate::test_complete!("mutual_exclusion_holds_under_lock_and_catches_unlocked_overlap");
+    }
+
+    #[test]
+    fn occurrence_bounds_report_repeats_and_missing_keys() {
+        init_test("occurrence_bounds_report_repeats_and_missing_keys");
+        let trace = vec![
+            marker_event(1, 1, "ack", &[("id", "1")]),
+            marker_event(2, 2, "ack", &[("id", "2")]),
+            marker_event(3, 1, "ack", &[("id", "1")]),
+        ];
+        let at_most = OrderingOracle::new()
+            .with_rule(OrderingRule::at_most_once("ack").keyed_by("id"));
+        let violations = at_most.check(&trace).expect_err("id 1 acked twice");
+        let repeated = violations.len() == 1
+            && violations[0].kind == OrderingViolationKind::Repeated
+            && violations[0].key.as_deref() == Some("1");
+        crate::assert_with_log!(repeated, "repeat of id 1", true, violations);
+
+        let rule = OrderingRule::exactly_once("ack")
+            .keyed_by("id")
+            .expecting_keys(["2", "3"]);
+        let exactly = OrderingOracle::new().with_rule(rule);
+        let violations = exactly.check(&trace).expect_err("repeat and missing key");
+        let kinds: Vec<_> = violations
+            .iter()
+            .map(|v| (v.kind, v.key.as_deref()))
+            .collect();
+        let expected = vec![
+            (OrderingViolationKind::Repeated, Some("1")),
+            (OrderingViolationKind::Missing, Some("3")),
+        ];
+        crate::assert_with_log!(kinds == expected, "kinds", expected, kinds);
+
+        let missing = OrderingOracle::new()
+            .with_rule(OrderingRule::exactly_once("shutdown"));
+        let verdict = missing.check(&trace);
+        crate::assert_with_log!(verdict.is_err(), "unkeyed missing", "Err", verdict);
+        crate::test_complete!("occurrence_bounds_report_repeats_and_missing_keys");
+    }
+
+    #[test]
+    fn rules_deserialize_from_yaml() {
+        init_test("rules_deserialize_from_yaml");
+        let yaml = "
+- rule: happens_before
+  before: db_commit
+  after: cache_invalidate
+  key: key
+- rule: exactly_once
+  marker: ack
+  key: id
+  expected_keys: [\"1\"]
+";
+        let rules: Vec<OrderingRule> = serde_yaml::from_str(yaml).expect("parse rules");
+        let expected = vec![
+            commit_before_invalidate(),
+            OrderingRule::exactly_once("ack")
+                .keyed_by("id")
+                .expecting_keys(["1"]),
+        ];
+        crate::assert_with_log!(rules == expected, "rules", expected, rules);
+        let malformed = OrderingRule::happens_before("a", "a").validate();
+        crate::assert_with_log!(malformed.is_some(), "same marker twice", true, malformed);
+        crate::test_complete!("rules_deserialize_from_yaml");
+    }
+
+    #[test]
+    fn evaluation_stays_linear_on_large_traces() {
+        init_test("evaluation_stays_linear_on_large_traces");
+        let trace: Vec<_> = (0..50_000_u64)
+            .flat_map(|i| {
+                let key = (i % 1024).to_string();
+                let task = (i % 64) as u32;
+                [
+                    marker_event(4 * i, task, "db_commit", &[("key", &key)]),
+                    marker_event(4 * i + 1, task, "cache_invalidate", &[("key", &key)]),
+                    marker_event(4 * i + 2, task, "flush_begin", &[("table", &key)]),
+                    marker_event(4 * i + 3, task, "flush_end", &[("table", &key)]),
+                ]
+            })
+            .collect();
+        let oracle = OrderingOracle::new()
+            .with_rule(commit_before_invalidate())
+            .with_rule(flush_exclusion())
+            .with_rule(OrderingRule::at_most_once("cache_invalidate").keyed_by("key"));
+
+        let started = Instant::now();
+        let verdict = oracle.check(&trace);
+        let elapsed = started.elapsed();
+        // Every key is invalidated 50 times; only the repeat rule fires, and
+        // it reports each key once.
+        let repeats = verdict.as_ref().err().map_or(0, Vec::len);
+        crate::assert_with_log!(repeats == 1024, "one report per key", 1024, repeats);
+        let bounded = elapsed < Duration::from_secs(10);
+        crate::assert_with_log!(bounded, "200k markers evaluated", "< 10s", elapsed);
+        crate::test_complete!("evaluation_stays_linear_on_large_traces");
+    }
+}
@@ -6,7 +6,7 @@
 //! - Trace capture for replay
 //! - Chaos injection for stress testing
 
-use super::config::LabConfig;
+use super::config::{LabConfig, LabResourceLimits};
 use super::oracle::OracleSuite;
 use crate::lab::chaos::{ChaosRng, ChaosStats};
 use crate::record::ObligationKind;
@@ -16,8 +16,12 @@ use crate::runtime::config::ObligationLeakResponse;
 use crate::runtime::deadline_monitor::{
     DeadlineMonitor, DeadlineWarning, MonitorConfig, default_warning_handler,
 };
+use crate::runtime::embedded::{TurnBudget, TurnResult};
 use crate::runtime::reactor::LabReactor;
-use crate::runtime::scheduler::{DispatchLane, ScheduleCertificate};
+use crate::runtime::scheduler::{
+    DispatchLane, RegionFairQueue, RegionFairness, RegionVruntime, ScheduleCertificate,
+};
+use crate::runtime::shutdown_report::ShutdownReport;
 use crate::time::VirtualClock;
 use crate::trace::TraceBufferHandle;
 use crate::trace::crashpack::{
@@ -31,10 +35,11 @@ use crate::trace::scoring::seed_fingerprint;
 use crate::trace::{TraceData, TraceEvent, check_refinement_firewall};
 use crate::trace::{canonicalize::trace_fingerprint, certificate::TraceCertificate};
 use crate::types::Time;
-use crate::types::{ObligationId, RegionId, TaskId};
+use crate::types::{CancelReason, ObligationId, RegionId, TaskId};
 use crate::util::det_hash::{DetHashMap, DetHashSet};
 use crate::util::{DetEntropy, DetRng};
 use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::task::{Context, Poll, Waker};
@@ -66,6 +71,9 @@ pub enum AutoAdvanceTermination {
     /// The runtime was stuck (scheduler empty, no pending deadlines, not
     /// quiescent) for 1 000 consecutive iterations and bailed out.
     StuckBailout,
+    /// Nothing can run except paused tasks that already hold a wakeup;
+    /// resuming them would let the run continue.
+    BlockedByPauses,
 }
 
 impl fmt::Display for AutoAdvanceTermination {
@@ -74,10 +82,40 @@ impl fmt::Display for AutoAdvanceTermination {
             Self::Quiescent => f.write_str("quiescent"),
             Self::StepLimitReached => f.write_str("step-limit-reached"),
             Self::StuckBailout => f.write_str("stuck-bailout"),
+            Self::BlockedByPauses => f.write_str("blocked-by-pauses"),
         }
     }
 }
 
+/// What a lab pause applies to.
+///
+/// See [`LabRuntime::pause_task`] and [`LabRuntime::pause_region`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PauseTarget {
+    /// A single task.
+    Task(TaskId),
+    /// Every current and future task of a region and its descendants.
+    Region(RegionId),
+}
+
+impl fmt::Display for PauseTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Task(task) => write!(f, "task {task}"),
+            Self::Region(region) => write!(f, "region {region}"),
+        }
+    }
+}
+
+/// A pause or resume scripted for a given step by
+/// [`LabRuntime::schedule_pause`].
+#[derive(Debug, Clone, Copy)]
+struct ScriptedPause {
+    step: u64,
+    target: PauseTarget,
+    pause: bool,
+}
+
 /// Report from a [`LabRuntime::run_with_auto_advance`] execution.
 ///
 /// Captures statistics about automatic time advancement during the run.
@@ -114,6 +152,221 @@ impl VirtualTimeReport {
     }
 }
 
+/// Resource dimension accounted by the lab runtime's resource certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LabResource {
+    /// Scheduler steps executed.
+    Steps,
+    /// Concurrently live (non-terminal) tasks.
+    LiveTasks,
+    /// Concurrently pending obligations.
+    PendingObligations,
+    /// Tracked buffer memory reported via [`LabRuntime::set_tracked_memory_bytes`].
+    TrackedMemoryBytes,
+    /// Total virtual time elapsed since the runtime epoch.
+    VirtualTimeNanos,
+}
+
+impl LabResource {
+    /// Every accounted resource, in stable report order.
+    pub const ALL: [Self; 5] = [
+        Self::Steps,
+        Self::LiveTasks,
+        Self::PendingObligations,
+        Self::TrackedMemoryBytes,
+        Self::VirtualTimeNanos,
+    ];
+
+    /// Stable snake-case name used in reports and scenario `limits:` keys.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Steps => "steps",
+            Self::LiveTasks => "live_tasks",
+            Self::PendingObligations => "pending_obligations",
+            Self::TrackedMemoryBytes => "tracked_memory_bytes",
+            Self::VirtualTimeNanos => "virtual_time_nanos",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for LabResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Resource-usage certificate: measured peaks for a lab run.
+///
+/// Every field is derived from deterministic runtime state, so two runs with
+/// the same seed produce identical certificates and trends can be compared
+/// across commits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LabResourceUsage {
+    /// Total scheduler steps executed.
+    pub steps: u64,
+    /// Peak number of concurrently live tasks.
+    pub peak_live_tasks: u64,
+    /// Peak number of concurrently pending obligations.
+    pub peak_pending_obligations: u64,
+    /// Peak tracked buffer memory in bytes, when any memory was reported.
+    pub peak_tracked_memory_bytes: Option<u64>,
+    /// Total virtual time elapsed, in nanoseconds.
+    pub virtual_time_nanos: u64,
+}
+
+impl LabResourceUsage {
+    /// Returns the measured peak for `resource`, if it was measured.
+    #[must_use]
+    pub const fn value_for(&self, resource: LabResource) -> Option<u64> {
+        match resource {
+            LabResource::Steps => Some(self.steps),
+            LabResource::LiveTasks => Some(self.peak_live_tasks),
+            LabResource::PendingObligations => Some(self.peak_pending_obligations),
+            LabResource::TrackedMemoryBytes => self.peak_tracked_memory_bytes,
+            LabResource::VirtualTimeNanos => Some(self.virtual_time_nanos),
+        }
+    }
+
+    /// Combines two certificates, keeping the worst case of every dimension.
+    ///
+    /// Used by seed exploration to report worst-case peaks across runs.
+    #[must_use]
+    pub fn worst_case(&self, other: &Self) -> Self {
+        Self {
+            steps: self.steps.max(other.steps),
+            peak_live_tasks: self.peak_live_tasks.max(other.peak_live_tasks),
+            peak_pending_obligations: self
+                .peak_pending_obligations
+                .max(other.peak_pending_obligations),
+            peak_tracked_memory_bytes: self
+                .peak_tracked_memory_bytes
+                .max(other.peak_tracked_memory_bytes),
+            virtual_time_nanos: self.virtual_time_nanos.max(other.virtual_time_nanos),
+        }
+    }
+
+    /// Convert to JSON for artifact storage.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+
+        json!({
+            "steps": self.steps,
+            "peak_live_tasks": self.peak_live_tasks,
+            "peak_pending_obligations": self.peak_pending_obligations,
+            "peak_tracked_memory_bytes": self.peak_tracked_memory_bytes,
+            "virtual_time_nanos": self.virtual_time_nanos,
+        })
+    }
+}
+
+/// A resource limit that was exceeded during a lab run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LabResourceLimitViolation {
+    /// Resource whose limit was exceeded.
+    pub resource: LabResource,
+    /// Configured limit.
+    pub limit: u64,
+    /// Peak value measured over the whole run.
+    pub peak: u64,
+    /// Scheduler step at which the limit was first exceeded.
+    pub first_exceeded_step: u64,
+}
+
+impl LabResourceLimitViolation {
+    /// Convert to JSON for artifact storage.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+
+        json!({
+            "resource": self.resource.as_str(),
+            "limit": self.limit,
+            "peak": self.peak,
+            "first_exceeded_step": self.first_exceeded_step,
+        })
+    }
+}
+
+impl fmt::Display for LabResourceLimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "resource_limit:{}:limit={},peak={},first_exceeded_step={}",
+            self.resource, self.limit, self.peak, self.first_exceeded_step
+        )
+    }
+}
+
+/// Incremental resource accounting sampled once per scheduler step.
+#[derive(Debug, Default)]
+struct LabResourceTracker {
+    usage: LabResourceUsage,
+    tracked_memory_bytes: Option<u64>,
+    first_exceeded_step: [Option<u64>; LabResource::ALL.len()],
+}
+
+impl LabResourceTracker {
+    fn observe(
+        &mut self,
+        step: u64,
+        live_tasks: u64,
+        pending_obligations: u64,
+        now: Time,
+        limits: &LabResourceLimits,
+    ) {
+        let usage = &mut self.usage;
+        usage.steps = step;
+        usage.peak_live_tasks = usage.peak_live_tasks.max(live_tasks);
+        usage.peak_pending_obligations = usage.peak_pending_obligations.max(pending_obligations);
+        usage.peak_tracked_memory_bytes = usage
+            .peak_tracked_memory_bytes
+            .max(self.tracked_memory_bytes);
+        usage.virtual_time_nanos = now.as_nanos();
+
+        if limits.is_empty() {
+            return;
+        }
+        let current = [
+            Some(step),
+            Some(live_tasks),
+            Some(pending_obligations),
+            self.tracked_memory_bytes,
+            Some(now.as_nanos()),
+        ];
+        for resource in LabResource::ALL {
+            let slot = &mut self.first_exceeded_step[resource.index()];
+            if slot.is_none()
+                && let (Some(limit), Some(value)) =
+                    (limits.limit_for(resource), current[resource.index()])
+                && value > limit
+            {
+                *slot = Some(step);
+            }
+        }
+    }
+
+    fn violations(&self, limits: &LabResourceLimits) -> Vec<LabResourceLimitViolation> {
+        LabResource::ALL
+            .into_iter()
+            .filter_map(|resource| {
+                let first_exceeded_step = self.first_exceeded_step[resource.index()]?;
+                Some(LabResourceLimitViolation {
+                    resource,
+                    limit: limits.limit_for(resource)?,
+                    peak: self.usage.value_for(resource)?,
+                    first_exceeded_step,
+                })
+            })
+            .collect()
+    }
+}
+
 /// Structured report for a single lab runtime run.
 ///
 /// This is intended as a low-level building block for Spork app harnesses.
@@ -129,6 +382,9 @@ pub struct LabRunReport {
     pub steps_total: u64,
     /// Whether the runtime is quiescent at report time.
     pub quiescent: bool,
+    /// Whether the run is non-quiescent only because paused tasks hold
+    /// wakeups (see [`LabRuntime::is_blocked_by_pauses`]).
+    pub blocked_by_pauses: bool,
     /// Virtual time (nanoseconds since epoch) at report time.
     pub now_nanos: u64,
     /// Number of events in the current trace buffer snapshot.
@@ -158,6 +414,16 @@ pub struct LabRunReport {
     pub refinement_counterexample_prefix_len: Option<usize>,
     /// Whether refinement checks were skipped due to trace-buffer truncation.
     pub refinement_firewall_skipped_due_to_trace_truncation: bool,
+    /// Resource-usage certificate (measured peaks) for the run so far.
+    pub resource_usage: LabResourceUsage,
+    /// Configured resource limits that were exceeded.
+    ///
+    /// Each violation is also rendered into `invariant_violations`, so a
+    /// resource regression fails the run like any oracle failure.
+    pub resource_limit_violations: Vec<LabResourceLimitViolation>,
+    /// Per-region virtual runtime at report time; empty unless the runtime
+    /// runs with [`RegionFairness::RegionFair`].
+    pub region_vruntimes: Vec<RegionVruntime>,
 }
 
 impl LabRunReport {
@@ -177,6 +443,7 @@ impl LabRunReport {
             "steps_delta": self.steps_delta,
             "steps_total": self.steps_total,
             "quiescent": self.quiescent,
+            "blocked_by_pauses": self.blocked_by_pauses,
             "now_nanos": self.now_nanos,
             "trace": {
                 "len": self.trace_len,
@@ -200,6 +467,25 @@ impl LabRunReport {
                 "counterexample_prefix_len": self.refinement_counterexample_prefix_len,
                 "skipped_due_to_trace_truncation": self.refinement_firewall_skipped_due_to_trace_truncation,
             },
+            "resources": {
+                "usage": self.resource_usage.to_json(),
+                "limit_violations": self
+                    .resource_limit_violations
+                    .iter()
+                    .map(LabResourceLimitViolation::to_json)
+                    .collect::<Vec<_>>(),
+                "region_vruntimes": self
+                    .region_vruntimes
+                    .iter()
+                    .map(|entry| json!({
+                        "region": entry.region.to_string(),
+                        "weight": entry.weight,
+                        "vruntime": entry.vruntime,
+                        "dispatched": entry.dispatched,
+                        "ready": entry.ready,
+                    }))
+                    .collect::<Vec<_>>(),
+            },
         })
     }
 
@@ -328,6 +614,8 @@ pub struct LabConfigSummary {
     pub chaos: Option<ChaosConfigSummary>,
     /// Whether replay recording is enabled.
     pub replay_recording_enabled: bool,
+    /// How ready-lane dispatches are shared between regions.
+    pub region_fairness: RegionFairness,
 }
 
 impl LabConfigSummary {
@@ -345,6 +633,7 @@ impl LabConfigSummary {
             max_steps: config.max_steps,
             chaos: config.chaos.as_ref().map(ChaosConfigSummary::from_config),
             replay_recording_enabled: config.replay_recording.is_some(),
+            region_fairness: config.region_fairness,
         }
     }
 
@@ -378,6 +667,10 @@ impl LabConfigSummary {
         } else {
             0u8.hash(&mut h);
         }
+        // Hashed only when non-default so existing task-fair hashes are stable.
+        if self.region_fairness.is_region_fair() {
+            self.region_fairness.as_str().hash(&mut h);
+        }
         h.finish()
     }
 
@@ -397,6 +690,7 @@ impl LabConfigSummary {
             "max_steps": self.max_steps,
             "chaos": self.chaos.as_ref().map(ChaosConfigSummary::to_json),
             "replay_recording_enabled": self.replay_recording_enabled,
+            "region_fairness": self.region_fairness.as_str(),
         })
     }
 }
@@ -801,6 +1095,14 @@ pub struct LabRuntime {
     pub oracles: OracleSuite,
     /// Schedule certificate for determinism verification.
     certificate: ScheduleCertificate,
+    /// Resource accounting backing the run's resource certificate.
+    resources: LabResourceTracker,
+    /// Tasks paused directly with [`Self::pause_task`].
+    paused_tasks: BTreeSet<TaskId>,
+    /// Regions paused with [`Self::pause_region`]; covers their subtrees.
+    paused_regions: BTreeSet<RegionId>,
+    /// Scripted pause/resume actions not yet applied, ordered by step.
+    pause_script: Vec<ScriptedPause>,
 }
 
 impl LabRuntime {
@@ -816,6 +1118,16 @@ impl LabRuntime {
         let mut state = RuntimeState::with_reactor(lab_reactor.clone());
         state.trace = TraceBufferHandle::new(config.trace_capacity);
         state.set_logical_clock_mode(crate::trace::distributed::LogicalClockMode::Lamport);
+        state.set_deadline_clamp_slack(config.deadline_clamp_slack);
+        if config.region_fairness.is_region_fair() {
+            state.record_trace_event(|seq| {
+                TraceEvent::user_trace(
+                    seq,
+                    Time::ZERO,
+                    format!("scheduler fairness: {}", config.region_fairness),
+                )
+            });
+        }
         state.set_obligation_leak_response(if config.panic_on_obligation_leak {
             ObligationLeakResponse::Panic
         } else {
@@ -863,10 +1175,10 @@ impl LabRuntime {
             seen_io_tokens: DetHashSet::with_hasher(
                 crate::util::det_hash::DetBuildHasher::with_seed(config.seed),
             ),
-            scheduler: Arc::new(Mutex::new(LabScheduler::new(
-                config.worker_count,
-                config.seed,
-            ))),
+            scheduler: Arc::new(Mutex::new(
+                LabScheduler::new(config.worker_count, config.seed)
+                    .with_region_fairness(config.region_fairness),
+            )),
             config,
             rng,
             virtual_time: Time::ZERO,
@@ -879,6 +1191,10 @@ impl LabRuntime {
             deadline_monitor: None,
             oracles: OracleSuite::new(),
             certificate: ScheduleCertificate::new(),
+            resources: LabResourceTracker::default(),
+            paused_tasks: BTreeSet::new(),
+            paused_regions: BTreeSet::new(),
+            pause_script: Vec::new(),
         }
     }
 
@@ -900,6 +1216,64 @@ impl LabRuntime {
         self.steps
     }
 
+    /// Returns the resource-usage certificate measured so far.
+    #[must_use]
+    pub const fn resource_usage(&self) -> LabResourceUsage {
+        self.resources.usage
+    }
+
+    /// Sets the scheduling weight of `region`.
+    ///
+    /// Only meaningful under [`RegionFairness::RegionFair`]: the region's
+    /// share of ready-lane dispatches is proportional to its weight, which
+    /// defaults to [`DEFAULT_REGION_WEIGHT`](crate::runtime::scheduler::DEFAULT_REGION_WEIGHT).
+    /// The change applies from the next step on and is recorded in the
+    /// trace. Zero is treated as one.
+    pub fn set_region_weight(&mut self, region: RegionId, weight: u32) {
+        let weight = weight.max(1);
+        self.scheduler.lock().region_fair.set_weight(region, weight);
+        self.state.record_trace_event(|seq| {
+            TraceEvent::user_trace(
+                seq,
+                self.virtual_time,
+                format!(
+                    "region weight: region={region} weight={weight} step={}",
+                    self.steps
+                ),
+            )
+        });
+    }
+
+    /// Returns per-region virtual runtime accounting, ordered by region id.
+    ///
+    /// Empty unless the runtime is configured with
+    /// [`RegionFairness::RegionFair`].
+    #[must_use]
+    pub fn region_vruntimes(&self) -> Vec<RegionVruntime> {
+        self.scheduler.lock().region_fair.snapshot()
+    }
+
+    /// Reports the current amount of tracked buffer memory, in bytes.
+    ///
+    /// Memory is not measured automatically; components that account for
+    /// their buffers report here so the peak lands in the resource
+    /// certificate and is checked against
+    /// [`LabResourceLimits::max_tracked_memory_bytes`].
+    pub fn set_tracked_memory_bytes(&mut self, bytes: u64) {
+        self.resources.tracked_memory_bytes = Some(bytes);
+        self.observe_resources();
+    }
+
+    fn observe_resources(&mut self) {
+        self.resources.observe(
+            self.steps,
+            self.state.live_task_count() as u64,
+            self.state.pending_obligation_count() as u64,
+            self.virtual_time,
+            &self.config.resource_limits,
+        );
+    }
+
     /// Returns a reference to the configuration.
     #[must_use]
     pub const fn config(&self) -> &LabConfig {
@@ -1170,6 +1544,17 @@ impl LabRuntime {
                 break AutoAdvanceTermination::Quiescent;
             }
 
+            // Only paused tasks could run: report that rather than a hang.
+            if self.is_blocked_by_pauses() {
+                break AutoAdvanceTermination::BlockedByPauses;
+            }
+
+            // A scripted resume is still ahead; step toward it.
+            if self.pause_script.iter().any(|scripted| !scripted.pause) {
+                self.step();
+                continue;
+            }
+
             // Not quiescent but nothing to advance — try one more step
             // (there may be I/O or finalizers to process)
             stuck_counter = stuck_counter.saturating_add(1);
@@ -1190,6 +1575,94 @@ impl LabRuntime {
         }
     }
 
+    /// Cancels every open root region, drives the runtime with virtual-time
+    /// auto-advance for at most `budget`, and returns a [`ShutdownReport`].
+    ///
+    /// This is the lab counterpart of
+    /// [`Runtime::shutdown`](crate::runtime::Runtime::shutdown). Timers due
+    /// after the budget are not fired: virtual time stops at the budget
+    /// deadline and the report is marked incomplete.
+    pub fn shutdown(&mut self, budget: Duration) -> ShutdownReport {
+        let started_at = self.virtual_time;
+        self.state.begin_shutdown_report(started_at, budget);
+        let mut roots: Vec<RegionId> = self
+            .state
+            .regions
+            .iter()
+            .filter(|(_, region)| region.parent.is_none() && !region.state().is_terminal())
+            .map(|(_, region)| region.id)
+            .collect();
+        roots.sort();
+        let reason = CancelReason::shutdown().with_timestamp(started_at);
+        for root in roots {
+            let (tasks_to_cancel, wakes) =
+                self.state.cancel_request(root, &reason, None).into_parts();
+            {
+                let mut scheduler = self.scheduler.lock();
+                for (task, priority) in tasks_to_cancel {
+                    scheduler.schedule_cancel(task, priority);
+                }
+            }
+            wakes.dispatch();
+        }
+        self.state
+            .note_shutdown_fanout_dispatched(self.virtual_time);
+
+        let budget_nanos = u64::try_from(budget.as_nanos()).unwrap_or(u64::MAX);
+        let deadline = started_at.saturating_add_nanos(budget_nanos);
+        let mut stuck_counter: u32 = 0;
+        let incomplete_reason = loop {
+            if let Some(max) = self.config.max_steps {
+                if self.steps >= max {
+                    break Some(format!("step limit of {max} reached during shutdown"));
+                }
+            }
+
+            let is_empty = self.scheduler.lock().is_empty();
+            if !is_empty {
+                stuck_counter = 0;
+                self.step();
+                continue;
+            }
+
+            if let Some(next) = self.next_auto_advance_deadline() {
+                if next > deadline {
+                    self.advance_time_to(deadline);
+                    break Some(format!(
+                        "shutdown budget of {budget:?} exhausted with {} live tasks",
+                        self.state.live_task_count()
+                    ));
+                }
+                if next > self.virtual_time {
+                    self.advance_time_to(next);
+                }
+                self.pump_due_system_events();
+                continue;
+            }
+
+            if self.is_quiescent() {
+                break None;
+            }
+
+            stuck_counter = stuck_counter.saturating_add(1);
+            if stuck_counter > 1000 {
+                self.advance_time_to(deadline);
+                break Some(format!(
+                    "shutdown budget of {budget:?} exhausted with {} live tasks",
+                    self.state.live_task_count()
+                ));
+            }
+            self.step();
+        };
+
+        let report = self
+            .state
+            .take_shutdown_report(incomplete_reason)
+            .expect("shutdown recorder armed above");
+        report.emit();
+        report
+    }
+
     /// Pauses the virtual clock, freezing time at the current value.
     ///
     /// While paused, `advance_time()` and timer processing still work at the
@@ -1221,6 +1694,212 @@ impl LabRuntime {
         self.virtual_clock.is_paused()
     }
 
+    /// Pauses `task` for interactive debugging.
+    ///
+    /// A paused task is never dispatched but otherwise stays intact: wakeups
+    /// it receives while paused (a timer firing, a message arriving, a
+    /// cancellation) are parked and replayed by [`Self::resume_task`]. The
+    /// pause is recorded in the trace and in the replay recording.
+    pub fn pause_task(&mut self, task: TaskId) {
+        if self.paused_tasks.insert(task) {
+            self.suspend_task(task);
+            self.record_pause(PauseTarget::Task(task), true);
+        }
+    }
+
+    /// Resumes a task paused with [`Self::pause_task`].
+    ///
+    /// The task stays paused while a paused region still covers it.
+    pub fn resume_task(&mut self, task: TaskId) {
+        if self.paused_tasks.remove(&task) {
+            if !self.region_pause_covers(task) {
+                self.release_task(task);
+            }
+            self.record_pause(PauseTarget::Task(task), false);
+        }
+    }
+
+    /// Pauses every current and future task of `region` and its descendants.
+    ///
+    /// Tasks spawned into the subtree while it is paused start out paused.
+    pub fn pause_region(&mut self, region: RegionId) {
+        if self.paused_regions.insert(region) {
+            self.sync_region_pauses();
+            self.record_pause(PauseTarget::Region(region), true);
+        }
+    }
+
+    /// Resumes a region paused with [`Self::pause_region`].
+    ///
+    /// Tasks that are also paused directly, or covered by another paused
+    /// region, stay paused.
+    pub fn resume_region(&mut self, region: RegionId) {
+        if self.paused_regions.remove(&region) {
+            for task in self.region_subtree_tasks(region) {
+                if !self.paused_tasks.contains(&task) && !self.region_pause_covers(task) {
+                    self.release_task(task);
+                }
+            }
+            self.record_pause(PauseTarget::Region(region), false);
+        }
+    }
+
+    /// Scripts a pause of `target` over the step range `steps`.
+    ///
+    /// The pause takes effect at the start of step `steps.start` and is
+    /// lifted at the start of step `steps.end`, so a scenario can say
+    /// "pause task X between steps 100 and 250" and replay it exactly.
+    pub fn schedule_pause(&mut self, target: PauseTarget, steps: std::ops::Range<u64>) {
+        self.pause_script.push(ScriptedPause {
+            step: steps.start,
+            target,
+            pause: true,
+        });
+        self.pause_script.push(ScriptedPause {
+            step: steps.end,
+            target,
+            pause: false,
+        });
+        self.pause_script.sort_by_key(|scripted| scripted.step);
+    }
+
+    /// Returns every paused task, including those paused through a region,
+    /// ordered by id.
+    #[must_use]
+    pub fn paused_tasks(&self) -> Vec<TaskId> {
+        self.scheduler.lock().paused.iter().copied().collect()
+    }
+
+    /// Returns true when the run cannot progress until a pause is lifted.
+    ///
+    /// The runtime is not quiescent, nothing is runnable, no timer or reactor
+    /// deadline is pending, no scripted resume is outstanding, and at least
+    /// one paused task holds a wakeup. A run stuck without any parked wakeup
+    /// is a genuine deadlock rather than a pause.
+    #[must_use]
+    pub fn is_blocked_by_pauses(&self) -> bool {
+        if self.is_quiescent()
+            || self.pause_script.iter().any(|scripted| !scripted.pause)
+            || self.next_auto_advance_deadline().is_some()
+        {
+            return false;
+        }
+        let scheduler = self.scheduler.lock();
+        scheduler.is_empty() && scheduler.parked_count() > 0
+    }
+
+    fn set_paused(&mut self, target: PauseTarget, paused: bool) {
+        match (target, paused) {
+            (PauseTarget::Task(task), true) => self.pause_task(task),
+            (PauseTarget::Task(task), false) => self.resume_task(task),
+            (PauseTarget::Region(region), true) => self.pause_region(region),
+            (PauseTarget::Region(region), false) => self.resume_region(region),
+        }
+    }
+
+    /// Applies the scripted pauses and resumes due at the current step.
+    fn apply_pause_script(&mut self) {
+        let due = self
+            .pause_script
+            .iter()
+            .take_while(|scripted| scripted.step <= self.steps)
+            .count();
+        let due: Vec<ScriptedPause> = self.pause_script.drain(..due).collect();
+        for scripted in due {
+            self.set_paused(scripted.target, scripted.pause);
+        }
+    }
+
+    /// Pauses tasks that joined a paused region's subtree since the last step.
+    fn sync_region_pauses(&self) {
+        for &region in &self.paused_regions {
+            for task in self.region_subtree_tasks(region) {
+                if !self.scheduler.lock().is_paused(task) {
+                    self.suspend_task(task);
+                }
+            }
+        }
+    }
+
+    fn suspend_task(&self, task: TaskId) {
+        let priority = self
+            .state
+            .task(task)
+            .and_then(|record| record.cx_inner.as_ref())
+            .map_or(0, |inner| inner.read().budget.priority);
+        self.scheduler.lock().pause(task, priority);
+    }
+
+    fn release_task(&mut self, task: TaskId) {
+        // Paused steps do not count toward futurelock idle time.
+        let steps = self.steps;
+        let _ = self
+            .state
+            .update_task(task, |record| record.mark_polled(steps));
+        self.scheduler.lock().resume(task);
+    }
+
+    /// Returns true if a paused region owns `task`, directly or through an
+    /// ancestor.
+    fn region_pause_covers(&self, task: TaskId) -> bool {
+        if self.paused_regions.is_empty() {
+            return false;
+        }
+        let mut region = self.state.task(task).map(|record| record.owner);
+        while let Some(id) = region {
+            if self.paused_regions.contains(&id) {
+                return true;
+            }
+            region = self.state.region(id).and_then(|record| record.parent);
+        }
+        false
+    }
+
+    /// Returns the live tasks of `region` and its descendants, ordered by id.
+    fn region_subtree_tasks(&self, region: RegionId) -> Vec<TaskId> {
+        let mut tasks = Vec::new();
+        let mut pending = vec![region];
+        while let Some(id) = pending.pop() {
+            let Some(record) = self.state.region(id) else {
+                continue;
+            };
+            tasks.extend(record.task_ids().into_iter().filter(|&task| {
+                self.state
+                    .task(task)
+                    .is_some_and(|task| !task.state.is_terminal())
+            }));
+            pending.extend(record.child_ids());
+        }
+        tasks.sort();
+        tasks.dedup();
+        tasks
+    }
+
+    fn record_pause(&mut self, target: PauseTarget, paused: bool) {
+        let kind = if paused { "pause" } else { "resume" };
+        let (task, region) = match target {
+            PauseTarget::Task(task) => (Some(task), None),
+            PauseTarget::Region(region) => (None, Some(region)),
+        };
+        self.state.record_trace_event(|seq| {
+            TraceEvent::new(
+                seq,
+                self.virtual_time,
+                TraceEventKind::ChaosInjection,
+                TraceData::Chaos {
+                    kind: kind.to_string(),
+                    task,
+                    detail: format!("lab {kind}: {target} step={}", self.steps),
+                },
+            )
+        });
+        if paused {
+            self.replay_recorder.record_pause_injection(task, region);
+        } else {
+            self.replay_recorder.record_resume_injection(task, region);
+        }
+    }
+
     /// Injects a clock skew by jumping time forward by `skew_nanos`.
     ///
     /// This simulates clock drift or NTP corrections. A warning is logged
@@ -1244,7 +1923,9 @@ impl LabRuntime {
 
     /// Runs until quiescent or max steps reached.
     ///
-    /// Returns the number of steps executed.
+    /// Also stops early when the run is [blocked by
+    /// pauses](Self::is_blocked_by_pauses), since no further step could make
+    /// progress. Returns the number of steps executed.
     pub fn run_until_quiescent(&mut self) -> u64 {
         let start_steps = self.steps;
 
@@ -1254,6 +1935,9 @@ impl LabRuntime {
                     break;
                 }
             }
+            if self.is_blocked_by_pauses() {
+                break;
+            }
             self.step();
         }
 
@@ -1295,6 +1979,52 @@ impl LabRuntime {
         self.steps - start_steps
     }
 
+    fn has_turn_work(&self) -> bool {
+        !self.scheduler.lock().is_empty()
+            || !self.spawn_mailbox.handle_cancels_are_empty()
+            || self.state.has_deferred_cancel_dispatches()
+    }
+
+    /// Runs one bounded turn, the lab counterpart of
+    /// [`EmbeddedDriver::turn`](crate::runtime::EmbeddedDriver::turn).
+    ///
+    /// Fires due timers and lab-reactor events, then steps like
+    /// [`Self::run_until_idle`] for at most `budget.max_polls()` steps.
+    /// Virtual time never moves inside a turn: the caller advances it, just
+    /// as a host loop sleeps until the returned deadline. The budget's time
+    /// limit is ignored so that a turn sequence stays a pure function of the
+    /// seed and the host's tick schedule.
+    pub fn turn(&mut self, budget: TurnBudget) -> TurnResult {
+        self.pump_due_system_events();
+        let mut polled = 0;
+        let mut budget_exhausted = false;
+        loop {
+            if self.config.max_steps.is_some_and(|max| self.steps >= max) {
+                break;
+            }
+            self.drain_handle_cancel_requests();
+            self.drain_deferred_cancel_dispatches();
+            if !self.has_turn_work() {
+                break;
+            }
+            if polled >= budget.max_polls() {
+                budget_exhausted = true;
+                break;
+            }
+            self.step();
+            polled += 1;
+        }
+
+        TurnResult {
+            polled,
+            budget_exhausted,
+            more_work: self.has_turn_work(),
+            next_deadline: self.next_auto_advance_deadline(),
+            now: self.virtual_time,
+            quiescent: self.is_quiescent(),
+        }
+    }
+
     /// Runs until quiescent (or `max_steps` is reached) and returns a structured report.
     #[must_use]
     pub fn run_until_quiescent_with_report(&mut self) -> LabRunReport {
@@ -1565,7 +2295,11 @@ impl LabRuntime {
     fn report_with_steps_delta(&mut self, steps_delta: u64) -> LabRunReport {
         let seed = self.config.seed;
         let quiescent = self.is_quiescent();
+        let blocked_by_pauses = self.is_blocked_by_pauses();
         let now = self.now();
+        self.observe_resources();
+        let resource_usage = self.resources.usage;
+        let resource_limit_violations = self.resources.violations(&self.config.resource_limits);
 
         let trace_events = self.trace().snapshot();
         let trace_len = trace_events.len();
@@ -1657,6 +2391,7 @@ impl LabRuntime {
                  seed={seed},total_pushed={trace_total_pushed},buffered={trace_buffered_len}"
             ));
         }
+        invariant_violations.extend(resource_limit_violations.iter().map(ToString::to_string));
         invariant_violations.sort();
         invariant_violations.dedup();
 
@@ -1665,6 +2400,7 @@ impl LabRuntime {
             steps_delta,
             steps_total: self.steps(),
             quiescent,
+            blocked_by_pauses,
             now_nanos: now.as_nanos(),
             trace_len,
             trace_fingerprint,
@@ -1682,6 +2418,9 @@ impl LabRuntime {
             refinement_firewall_event_seq,
             refinement_counterexample_prefix_len,
             refinement_firewall_skipped_due_to_trace_truncation,
+            resource_usage,
+            resource_limit_violations,
+            region_vruntimes: self.region_vruntimes(),
         }
     }
 
@@ -1895,8 +2634,11 @@ impl LabRuntime {
     #[allow(clippy::too_many_lines)]
     fn step(&mut self) {
         self.steps += 1;
+        self.observe_resources();
         self.drain_deferred_cancel_dispatches();
         self.drain_spawn_admissions();
+        self.apply_pause_script();
+        self.sync_region_pauses();
         // Admission publication can invoke a retained cancellation Waker.
         // Consume any command it enqueued before selecting runnable work.
         self.drain_handle_cancel_requests();
@@ -1931,6 +2673,8 @@ impl LabRuntime {
         let now = self.now();
         let (task_id, dispatch_lane) = {
             let mut sched = self.scheduler.lock();
+            let state = &self.state;
+            sched.resolve_regions(|task| state.task(task).map(|record| record.owner));
             if let Some((tid, lane)) = sched.pop_for_worker(worker_hint, rng_value, now) {
                 (tid, lane)
             } else if let Some(tid) = sched.steal_for_worker(worker_hint, rng_value.rotate_left(17))
@@ -1974,7 +2718,12 @@ impl LabRuntime {
                         }
                     }
 
-                    guard.budget.priority
+                    // Tasks holding an inheriting lock run at their
+                    // highest-priority waiter's priority.
+                    guard
+                        .budget
+                        .priority
+                        .max(guard.priority_inheritance.inherited())
                 });
 
                 (
@@ -1992,6 +2741,9 @@ impl LabRuntime {
             _ => Waker::from(Arc::new(TaskWaker {
                 task_id,
                 priority,
+                inheritance: cx_inner
+                    .as_ref()
+                    .map(|inner| Arc::clone(&inner.read().priority_inheritance)),
                 scheduler: self.scheduler.clone(),
             })),
         };
@@ -2855,9 +3607,11 @@ impl LabRuntime {
 
         let current_step = self.steps;
         let mut violations = Vec::new();
+        let scheduler = self.scheduler.lock();
 
         for (_, task) in self.state.tasks_iter() {
-            if task.state.is_terminal() {
+            // A paused task is idle by request, not futurelocked.
+            if task.state.is_terminal() || scheduler.is_paused(task.id) {
                 continue;
             }
 
@@ -2883,6 +3637,7 @@ impl LabRuntime {
                 });
             }
         }
+        drop(scheduler);
 
         violations
     }
@@ -3199,6 +3954,31 @@ struct PendingSpuriousWake {
     remaining: usize,
 }
 
+/// A wakeup received by a paused task, replayed when it resumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParkedWake {
+    Ready(u8),
+    Cancel(u8),
+    Timed(Time),
+}
+
+impl ParkedWake {
+    /// Folds two wakeups into one: cancel outranks ready, ready outranks
+    /// timed, and the strongest priority or earliest deadline wins.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Cancel(a), Self::Cancel(b) | Self::Ready(b))
+            | (Self::Ready(b), Self::Cancel(a)) => Self::Cancel(a.max(b)),
+            (cancel @ Self::Cancel(_), Self::Timed(_))
+            | (Self::Timed(_), cancel @ Self::Cancel(_)) => cancel,
+            (Self::Ready(a), Self::Ready(b)) => Self::Ready(a.max(b)),
+            (ready @ Self::Ready(_), Self::Timed(_))
+            | (Self::Timed(_), ready @ Self::Ready(_)) => ready,
+            (Self::Timed(a), Self::Timed(b)) => Self::Timed(a.min(b)),
+        }
+    }
+}
+
 #[derive(Debug)]
 /// Deterministic lab scheduler with per-worker queues.
 ///
@@ -3213,6 +3993,16 @@ pub struct LabScheduler {
     next_worker: usize,
     cancel_streak: Vec<usize>,
     cancel_streak_limit: usize,
+    fairness: RegionFairness,
+    /// Region-fair ready queue, shared by all workers.
+    region_fair: RegionFairQueue,
+    /// Ready tasks woken since the last step whose region is not resolved
+    /// yet; wakers only know the task id.
+    unresolved: Vec<(TaskId, u8)>,
+    /// Tasks paused by the lab; they are never dispatched.
+    paused: BTreeSet<TaskId>,
+    /// Wakeups received by paused tasks, replayed on resume.
+    parked: BTreeMap<TaskId, ParkedWake>,
 }
 
 impl LabScheduler {
@@ -3233,9 +4023,19 @@ impl LabScheduler {
             next_worker: 0,
             cancel_streak: vec![0; count],
             cancel_streak_limit,
+            fairness: RegionFairness::TaskFair,
+            region_fair: RegionFairQueue::new(),
+            unresolved: Vec::new(),
+            paused: BTreeSet::new(),
+            parked: BTreeMap::new(),
         }
     }
 
+    const fn with_region_fairness(mut self, fairness: RegionFairness) -> Self {
+        self.fairness = fairness;
+        self
+    }
+
     /// Returns true if no tasks are currently scheduled.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -3248,6 +4048,63 @@ impl LabScheduler {
         self.cancel_streak_limit
     }
 
+    /// Returns true if `task` is paused.
+    #[must_use]
+    pub fn is_paused(&self, task: TaskId) -> bool {
+        self.paused.contains(&task)
+    }
+
+    /// Returns the number of paused tasks holding a wakeup they will run on
+    /// once resumed.
+    #[must_use]
+    pub fn parked_count(&self) -> usize {
+        self.parked.len()
+    }
+
+    /// Pauses `task`, pulling it out of the run queues if it is scheduled.
+    ///
+    /// A queued wakeup is parked with `priority` and replayed on resume;
+    /// a timed wakeup resumes in the ready lane.
+    fn pause(&mut self, task: TaskId, priority: u8) {
+        if !self.paused.insert(task) || !self.scheduled.remove(&task) {
+            return;
+        }
+        let cancel = self
+            .workers
+            .iter()
+            .any(|worker| worker.is_in_cancel_lane(task));
+        self.take_region_fair(task);
+        for worker in &mut self.workers {
+            worker.remove(task);
+        }
+        let wake = if cancel {
+            ParkedWake::Cancel(priority)
+        } else {
+            ParkedWake::Ready(priority)
+        };
+        self.park(task, wake);
+    }
+
+    /// Resumes `task` and reschedules any wakeup it received while paused.
+    fn resume(&mut self, task: TaskId) {
+        if !self.paused.remove(&task) {
+            return;
+        }
+        match self.parked.remove(&task) {
+            Some(ParkedWake::Ready(priority)) => self.schedule(task, priority),
+            Some(ParkedWake::Cancel(priority)) => self.schedule_cancel(task, priority),
+            Some(ParkedWake::Timed(deadline)) => self.schedule_timed(task, deadline),
+            None => {}
+        }
+    }
+
+    fn park(&mut self, task: TaskId, wake: ParkedWake) {
+        self.parked
+            .entry(task)
+            .and_modify(|parked| *parked = parked.merge(wake))
+            .or_insert(wake);
+    }
+
     #[inline]
     fn set_assignment(&mut self, task: TaskId, worker: usize) {
         let slot = task.arena_index().index() as usize;
@@ -3274,15 +4131,76 @@ impl LabScheduler {
     }
 
     /// Schedules a task in the ready lane on its assigned worker.
+    ///
+    /// In region-fair mode the task is staged until the next step resolves
+    /// its region and moves it into the region-fair queue. Scheduling an
+    /// already-queued ready task at a higher priority promotes it in place.
     pub fn schedule(&mut self, task: TaskId, priority: u8) {
+        if self.paused.contains(&task) {
+            self.park(task, ParkedWake::Ready(priority));
+            return;
+        }
         if !self.scheduled.insert(task) {
+            self.promote_ready(task, priority);
+            return;
+        }
+
+        self.enqueue_ready(task, priority);
+    }
+
+    /// Raises the priority of a queued ready task, e.g. when it inherits the
+    /// priority of a lock waiter.
+    fn promote_ready(&mut self, task: TaskId, priority: u8) {
+        if let Some((_, queued)) = self
+            .unresolved
+            .iter_mut()
+            .find(|(queued_task, _)| *queued_task == task)
+        {
+            *queued = (*queued).max(priority);
+            return;
+        }
+        if self.region_fair.promote(task, priority) {
             return;
         }
+        let slot = task.arena_index().index() as usize;
+        if let Some(Some(worker)) = self.assignments.get(slot).copied() {
+            self.workers[worker].promote_ready(task, priority);
+        }
+    }
 
+    fn enqueue_ready(&mut self, task: TaskId, priority: u8) {
+        if self.fairness.is_region_fair() {
+            self.unresolved.push((task, priority));
+            return;
+        }
         let worker = self.assign_worker(task);
         self.workers[worker].schedule(task, priority);
     }
 
+    /// Moves staged ready tasks into the region-fair queue.
+    ///
+    /// Tasks whose region cannot be resolved stay task-fair on their worker.
+    fn resolve_regions(&mut self, region_of: impl Fn(TaskId) -> Option<RegionId>) {
+        for (task, priority) in std::mem::take(&mut self.unresolved) {
+            if let Some(region) = region_of(task) {
+                self.region_fair.push(region, task, priority);
+            } else {
+                let worker = self.assign_worker(task);
+                self.workers[worker].schedule(task, priority);
+            }
+        }
+    }
+
+    /// Removes a task from the region-fair structures, if it is there.
+    fn take_region_fair(&mut self, task: TaskId) -> bool {
+        if self.region_fair.remove(task) {
+            return true;
+        }
+        let staged = self.unresolved.len();
+        self.unresolved.retain(|(queued, _)| *queued != task);
+        self.unresolved.len() != staged
+    }
+
     /// Injects ready-lane wakeups that should survive normal deduplication.
     ///
     /// The first wake is scheduled immediately when needed. Remaining wakeups
@@ -3295,8 +4213,7 @@ impl LabScheduler {
 
         let mut remaining = count;
         if self.scheduled.insert(task) {
-            let worker = self.assign_worker(task);
-            self.workers[worker].schedule(task, priority);
+            self.enqueue_ready(task, priority);
             remaining = remaining.saturating_sub(1);
         }
 
@@ -3318,12 +4235,22 @@ impl LabScheduler {
 
     /// Schedules or promotes a task into the cancel lane.
     pub fn schedule_cancel(&mut self, task: TaskId, priority: u8) {
+        if self.paused.contains(&task) {
+            self.park(task, ParkedWake::Cancel(priority));
+            return;
+        }
         if self.scheduled.insert(task) {
             let worker = self.assign_worker(task);
             self.workers[worker].schedule_cancel(task, priority);
             return;
         }
 
+        if self.take_region_fair(task) {
+            let worker = self.assign_worker(task);
+            self.workers[worker].schedule_cancel(task, priority);
+            return;
+        }
+
         let slot = task.arena_index().index() as usize;
         if let Some(&Some(worker)) = self.assignments.get(slot) {
             self.workers[worker].move_to_cancel_lane(task, priority);
@@ -3332,6 +4259,10 @@ impl LabScheduler {
 
     /// Schedules a task in the timed lane on its assigned worker.
     pub fn schedule_timed(&mut self, task: TaskId, deadline: Time) {
+        if self.paused.contains(&task) {
+            self.park(task, ParkedWake::Timed(deadline));
+            return;
+        }
         if !self.scheduled.insert(task) {
             return;
         }
@@ -3371,6 +4302,14 @@ impl LabScheduler {
             return Some((task, DispatchLane::Timed));
         }
 
+        if let Some((task, _region)) = self.region_fair.pop(rng_hint) {
+            *cancel_streak = 0;
+            self.scheduled.remove(&task);
+            self.set_assignment(task, worker);
+            self.rearm_spurious_wake(task);
+            return Some((task, DispatchLane::Ready));
+        }
+
         if let Some(task) = self.workers[worker].pop_ready_only_with_hint(rng_hint) {
             *cancel_streak = 0;
             self.scheduled.remove(&task);
@@ -3421,6 +4360,9 @@ impl LabScheduler {
     fn forget_task(&mut self, task: TaskId) {
         self.scheduled.remove(&task);
         self.pending_spurious_wakes.remove(&task);
+        self.paused.remove(&task);
+        self.parked.remove(&task);
+        self.take_region_fair(task);
         let slot = task.arena_index().index() as usize;
         if slot < self.assignments.len() {
             self.assignments[slot] = None;
@@ -3446,13 +4388,20 @@ impl LabScheduler {
 struct TaskWaker {
     task_id: crate::types::TaskId,
     priority: u8,
+    /// Read at wake time so a priority boost inherited after this waker was
+    /// created still requeues the task at the boosted priority.
+    inheritance: Option<Arc<crate::sync::TaskPriorityNode>>,
     scheduler: Arc<Mutex<LabScheduler>>,
 }
 
 use std::task::Wake;
 impl Wake for TaskWaker {
     fn wake(self: Arc<Self>) {
-        self.scheduler.lock().schedule(self.task_id, self.priority);
+        let priority = self
+            .inheritance
+            .as_ref()
+            .map_or(self.priority, |node| self.priority.max(node.inherited()));
+        self.scheduler.lock().schedule(self.task_id, priority);
     }
 }
 
@@ -3627,6 +4576,7 @@ mod tests {
     use crate::runtime::deadline_monitor::{AdaptiveDeadlineConfig, WarningReason};
     #[cfg(unix)]
     use crate::runtime::reactor::{Event, Interest};
+    use crate::runtime::scheduler::DEFAULT_REGION_WEIGHT;
     use crate::types::{Budget, CancelKind, CancelReason, CxInner, Outcome, TaskId};
     use crate::util::ArenaIndex;
     use parking_lot::Mutex;
@@ -4705,6 +5655,444 @@ mod tests {
         crate::test_complete!("deterministic_multiworker_schedule");
     }
 
+    /// Spawns `crowd` tasks in one child region and a single task in
+    /// another; every poll bumps its region's counter and yields.
+    fn spawn_region_imbalance(
+        runtime: &mut LabRuntime,
+        crowd: usize,
+    ) -> (
+        RegionId,
+        RegionId,
+        Arc<std::sync::atomic::AtomicU64>,
+        Arc<std::sync::atomic::AtomicU64>,
+    ) {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let crowded = runtime
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("create crowded region");
+        let lonely = runtime
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("create lonely region");
+        let crowded_polls = Arc::new(AtomicU64::new(0));
+        let lonely_polls = Arc::new(AtomicU64::new(0));
+
+        let mut spawn = |region: RegionId, polls: &Arc<AtomicU64>| {
+            let polls = Arc::clone(polls);
+            let (task_id, _handle) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, async move {
+                    for _ in 0..1_000_000 {
+                        polls.fetch_add(1, Ordering::Relaxed);
+                        crate::runtime::yield_now::yield_now().await;
+                    }
+                })
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task_id, 0);
+        };
+        for _ in 0..crowd {
+            spawn(crowded, &crowded_polls);
+        }
+        spawn(lonely, &lonely_polls);
+        (crowded, lonely, crowded_polls, lonely_polls)
+    }
+
+    fn run_steps(runtime: &mut LabRuntime, steps: usize) {
+        for _ in 0..steps {
+            runtime.step_for_test();
+        }
+    }
+
+    #[test]
+    fn region_fair_shares_dispatches_by_weight() {
+        init_test("region_fair_shares_dispatches_by_weight");
+        use std::sync::atomic::Ordering;
+
+        // Task-fair: the lone task gets roughly one dispatch in a thousand.
+        let mut runtime = LabRuntime::new(LabConfig::new(41));
+        let (_, _, _, lonely_polls) = spawn_region_imbalance(&mut runtime, 1000);
+        run_steps(&mut runtime, 2000);
+        assert!(lonely_polls.load(Ordering::Relaxed) < 20);
+        assert!(runtime.region_vruntimes().is_empty());
+
+        // Region-fair with weights 1:3: the lone region gets three quarters.
+        let config = LabConfig::new(41).with_region_fairness(RegionFairness::RegionFair);
+        let mut runtime = LabRuntime::new(config);
+        let (crowded, lonely, crowded_polls, lonely_polls) =
+            spawn_region_imbalance(&mut runtime, 1000);
+        runtime.set_region_weight(lonely, 3 * DEFAULT_REGION_WEIGHT);
+        run_steps(&mut runtime, 2000);
+        let crowded_share = crowded_polls.load(Ordering::Relaxed);
+        let lonely_share = lonely_polls.load(Ordering::Relaxed);
+        assert!((490..=510).contains(&crowded_share), "{crowded_share}");
+        assert!((1490..=1510).contains(&lonely_share), "{lonely_share}");
+
+        let snapshot = runtime.region_vruntimes();
+        let entry = |region| {
+            snapshot
+                .iter()
+                .find(|entry| entry.region == region)
+                .copied()
+                .expect("region accounted")
+        };
+        assert_eq!(entry(lonely).weight, 3 * DEFAULT_REGION_WEIGHT);
+        assert_eq!(entry(crowded).weight, DEFAULT_REGION_WEIGHT);
+        assert!(entry(crowded).vruntime.abs_diff(entry(lonely).vruntime) <= 1024);
+
+        let report = runtime.report();
+        assert_eq!(report.region_vruntimes, runtime.region_vruntimes());
+        let json = report.to_json();
+        assert_eq!(
+            json["resources"]["region_vruntimes"]
+                .as_array()
+                .map(Vec::len),
+            Some(2)
+        );
+        crate::test_complete!("region_fair_shares_dispatches_by_weight");
+    }
+
+    #[test]
+    fn region_weight_change_is_deterministic() {
+        init_test("region_weight_change_is_deterministic");
+        use std::sync::atomic::Ordering;
+
+        let run = || {
+            let config = LabConfig::new(97)
+                .trace_capacity(1 << 16)
+                .with_region_fairness(RegionFairness::RegionFair);
+            let mut runtime = LabRuntime::new(config);
+            let (_, lonely, _, lonely_polls) = spawn_region_imbalance(&mut runtime, 100);
+            run_steps(&mut runtime, 400);
+            let before = lonely_polls.load(Ordering::Relaxed);
+            runtime.set_region_weight(lonely, 4 * DEFAULT_REGION_WEIGHT);
+            run_steps(&mut runtime, 500);
+            let after = lonely_polls.load(Ordering::Relaxed) - before;
+            let messages: Vec<String> = runtime
+                .trace()
+                .snapshot()
+                .iter()
+                .filter_map(|event| match &event.data {
+                    TraceData::Message(message) => Some(message.clone()),
+                    _ => None,
+                })
+                .collect();
+            (before, after, runtime.certificate().hash(), messages)
+        };
+
+        let (before, after, hash, messages) = run();
+        assert!((195..=205).contains(&before), "{before}");
+        assert!((395..=405).contains(&after), "{after}");
+        assert_eq!(
+            messages.first().map(String::as_str),
+            Some("scheduler fairness: region_fair")
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|message| message.starts_with("region weight:")
+                    && message.ends_with("weight=4096 step=400")),
+            "{messages:?}"
+        );
+
+        let (before2, after2, hash2, messages2) = run();
+        assert_eq!((before, after, hash), (before2, after2, hash2));
+        assert_eq!(messages, messages2);
+        crate::test_complete!("region_weight_change_is_deterministic");
+    }
+
+    /// Spawns a task that yields `yields` times, then runs `finish`.
+    fn spawn_yielding(
+        runtime: &mut LabRuntime,
+        region: RegionId,
+        yields: usize,
+        finish: impl FnOnce() + Send + 'static,
+    ) -> TaskId {
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                for _ in 0..yields {
+                    crate::runtime::yield_now::yield_now().await;
+                }
+                finish();
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+        task_id
+    }
+
+    /// Spawns a task that stays pending until it observes cancellation.
+    fn spawn_cancel_aware(runtime: &mut LabRuntime, region: RegionId) -> TaskId {
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async {
+                std::future::poll_fn(|_| {
+                    if crate::cx::Cx::with_current(|cx| cx.checkpoint().is_err()).unwrap_or(false) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await;
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+        task_id
+    }
+
+    fn is_live(runtime: &LabRuntime, task: TaskId) -> bool {
+        runtime
+            .state
+            .task(task)
+            .is_some_and(|record| !record.state.is_terminal())
+    }
+
+    #[test]
+    fn pause_window_reproduces_race() {
+        init_test("pause_window_reproduces_race");
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        // The consumer reads after two yields, the producer writes after one.
+        // Pausing the producer over steps 1..20 forces the stale read on
+        // every seed.
+        for seed in 0..8 {
+            let mut runtime = LabRuntime::with_seed(seed);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            let value = Arc::new(AtomicU64::new(0));
+            let observed = Arc::new(AtomicU64::new(u64::MAX));
+
+            let written = Arc::clone(&value);
+            let producer = spawn_yielding(&mut runtime, root, 1, move || {
+                written.store(1, Ordering::SeqCst);
+            });
+            let read = Arc::clone(&value);
+            let seen = Arc::clone(&observed);
+            spawn_yielding(&mut runtime, root, 2, move || {
+                seen.store(read.load(Ordering::SeqCst), Ordering::SeqCst);
+            });
+
+            runtime.schedule_pause(PauseTarget::Task(producer), 1..20);
+            runtime.run_until_quiescent();
+
+            assert_eq!(observed.load(Ordering::SeqCst), 0, "seed {seed}");
+            assert_eq!(value.load(Ordering::SeqCst), 1, "seed {seed}");
+            assert!(runtime.is_quiescent());
+            assert!(runtime.paused_tasks().is_empty());
+        }
+        crate::test_complete!("pause_window_reproduces_race");
+    }
+
+    #[test]
+    fn pause_blocked_run_is_distinct_from_deadlock() {
+        init_test("pause_blocked_run_is_distinct_from_deadlock");
+        let config = LabConfig::new(42)
+            .with_auto_advance()
+            .no_step_limit()
+            .futurelock_max_idle_steps(0);
+
+        // Genuine deadlock: a task nobody will ever wake.
+        let mut lab = LabRuntime::new(config.clone());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (stuck, _handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async {
+                std::future::pending::<()>().await;
+            })
+            .expect("create pending task");
+        lab.scheduler.lock().schedule(stuck, 0);
+        let report = lab.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::StuckBailout);
+        assert!(!lab.is_blocked_by_pauses());
+
+        // Pause-blocked: the paused task holds a wakeup it will run on.
+        let mut lab = LabRuntime::new(config);
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let task = spawn_yielding(&mut lab, root, 3, || {});
+        lab.pause_task(task);
+        let report = lab.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::BlockedByPauses);
+        assert!(lab.is_blocked_by_pauses());
+        assert_eq!(lab.paused_tasks(), vec![task]);
+        let pause_report = lab.report();
+        assert!(pause_report.blocked_by_pauses);
+        assert!(!pause_report.quiescent);
+        assert_eq!(pause_report.to_json()["blocked_by_pauses"], true);
+        assert!(is_live(&lab, task));
+
+        lab.resume_task(task);
+        let report = lab.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::Quiescent);
+        assert!(!is_live(&lab, task));
+        crate::test_complete!("pause_blocked_run_is_distinct_from_deadlock");
+    }
+
+    #[test]
+    fn paused_region_defers_cancel_drain_until_resume() {
+        init_test("paused_region_defers_cancel_drain_until_resume");
+        let mut runtime = LabRuntime::with_seed(5);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let child = runtime
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("create child region");
+        let first = spawn_cancel_aware(&mut runtime, child);
+        let second = spawn_cancel_aware(&mut runtime, child);
+        runtime.run_until_idle();
+        assert!(is_live(&runtime, first) && is_live(&runtime, second));
+
+        let (tasks, wakes) = runtime
+            .state
+            .cancel_request(child, &CancelReason::shutdown(), None)
+            .into_parts();
+        {
+            let mut scheduler = runtime.scheduler.lock();
+            for (task, priority) in tasks {
+                scheduler.schedule_cancel(task, priority);
+            }
+        }
+        wakes.dispatch();
+        runtime.pause_region(child);
+
+        runtime.run_until_quiescent();
+        assert!(runtime.is_blocked_by_pauses());
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(runtime.paused_tasks(), expected);
+        assert!(is_live(&runtime, first) && is_live(&runtime, second));
+
+        runtime.resume_region(child);
+        runtime.run_until_quiescent();
+        assert!(runtime.is_quiescent());
+        assert!(!is_live(&runtime, first) && !is_live(&runtime, second));
+        crate::test_complete!("paused_region_defers_cancel_drain_until_resume");
+    }
+
+    #[test]
+    fn paused_region_pauses_tasks_spawned_later() {
+        init_test("paused_region_pauses_tasks_spawned_later");
+        let mut runtime = LabRuntime::with_seed(6);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let child = runtime
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("create child region");
+        runtime.pause_region(root);
+
+        // Spawned into the paused subtree after the pause: starts paused.
+        let late = spawn_yielding(&mut runtime, child, 2, || {});
+        runtime.run_until_quiescent();
+        assert!(runtime.is_blocked_by_pauses());
+        assert_eq!(runtime.paused_tasks(), vec![late]);
+
+        // A direct pause outlives the region pause.
+        runtime.pause_task(late);
+        runtime.resume_region(root);
+        runtime.run_until_quiescent();
+        assert_eq!(runtime.paused_tasks(), vec![late]);
+        assert!(is_live(&runtime, late));
+
+        runtime.resume_task(late);
+        runtime.run_until_quiescent();
+        assert!(runtime.is_quiescent());
+        assert!(!is_live(&runtime, late));
+        crate::test_complete!("paused_region_pauses_tasks_spawned_later");
+    }
+
+    #[test]
+    fn pause_window_replays_identically() {
+        init_test("pause_window_replays_identically");
+        use crate::trace::recorder::chaos_kind;
+
+        let run = || {
+            let config = LabConfig::new(23)
+                .worker_count(2)
+                .with_default_replay_recording();
+            let mut runtime = LabRuntime::new(config);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            let paused = spawn_yielding(&mut runtime, root, 4, || {});
+            spawn_yielding(&mut runtime, root, 4, || {});
+            runtime.schedule_pause(PauseTarget::Task(paused), 2..6);
+            runtime.run_until_quiescent();
+            let details: Vec<String> = runtime
+                .trace()
+                .snapshot()
+                .iter()
+                .filter_map(|event| match &event.data {
+                    TraceData::Chaos { kind, detail, .. }
+                        if kind == "pause" || kind == "resume" =>
+                    {
+                        Some(detail.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            let replay = runtime
+                .finish_replay_trace()
+                .expect("replay recording enabled");
+            (paused, runtime.certificate().hash(), details, replay.events)
+        };
+
+        let (paused, hash, details, events) = run();
+        assert_eq!(
+            details,
+            vec![
+                format!("lab pause: task {paused} step=2"),
+                format!("lab resume: task {paused} step=6"),
+            ]
+        );
+        let pause_kinds: Vec<u8> = events
+            .iter()
+            .filter_map(|event| match event {
+                ReplayEvent::ChaosInjection { kind, task, .. }
+                    if *task == Some(paused.into()) =>
+                {
+                    Some(*kind)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(pause_kinds, vec![chaos_kind::PAUSE, chaos_kind::RESUME]);
+
+        let (_, hash2, details2, events2) = run();
+        assert_eq!(hash, hash2);
+        assert_eq!(details, details2);
+        assert_eq!(events, events2);
+        crate::test_complete!("pause_window_replays_identically");
+    }
+
+    #[test]
+    fn region_fair_single_region_matches_task_fair() {
+        init_test("region_fair_single_region_matches_task_fair");
+
+        let run = |fairness: RegionFairness| {
+            let config = LabConfig::new(5).with_region_fairness(fairness);
+            let mut runtime = LabRuntime::new(config);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            for n in 0..16u32 {
+                let (task_id, _handle) = runtime
+                    .state
+                    .create_task(root, Budget::INFINITE, async move {
+                        for _ in 0..n % 5 {
+                            crate::runtime::yield_now::yield_now().await;
+                        }
+                    })
+                    .expect("create task");
+                runtime.scheduler.lock().schedule(task_id, (n % 3) as u8);
+            }
+            let steps = runtime.run_until_quiescent();
+            (steps, runtime.certificate().hash())
+        };
+
+        // One region leaves nothing to share: the schedule must be identical.
+        assert_eq!(
+            run(RegionFairness::TaskFair),
+            run(RegionFairness::RegionFair)
+        );
+        crate::test_complete!("region_fair_single_region_matches_task_fair");
+    }
+
     #[test]
     fn run_until_quiescent_with_report_is_deterministic() {
         init_test("run_until_quiescent_with_report_is_deterministic");
@@ -5342,6 +6730,108 @@ mod tests {
         crate::test_complete!("report_hydrates_temporal_oracles_from_state_snapshot");
     }
 
+    #[test]
+    fn resource_limits_report_step_where_each_limit_was_first_exceeded() {
+        init_test("resource_limits_report_step_where_each_limit_was_first_exceeded");
+        let limits = LabResourceLimits {
+            max_steps: Some(2),
+            max_live_tasks: Some(2),
+            max_pending_obligations: Some(0),
+            max_tracked_memory_bytes: Some(1024),
+            max_virtual_time_nanos: Some(1_000),
+        };
+        let config = LabConfig::new(33)
+            .panic_on_leak(false)
+            .with_resource_limits(limits);
+        let mut runtime = LabRuntime::new(config);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        for _ in 0..3 {
+            let (task_id, _handle) = runtime
+                .state
+                .create_task(root, Budget::INFINITE, async {})
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task_id, 0);
+        }
+        // Never scheduled: holds the obligation below.
+        let (holder, _holder_handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect("create holder task");
+
+        // Step 1 observes four live tasks.
+        runtime.step_for_test();
+        runtime.set_tracked_memory_bytes(512);
+        // Step 2 reports memory above the limit.
+        runtime.step_for_test();
+        runtime.set_tracked_memory_bytes(4096);
+        // Step 3 is the first step above `max_steps`.
+        runtime.step_for_test();
+        let _obligation = runtime
+            .state
+            .create_obligation(ObligationKind::SendPermit, holder, root, None)
+            .expect("create obligation");
+        // Step 4 observes the pending obligation.
+        runtime.step_for_test();
+        runtime.advance_time(2_000);
+
+        let report = runtime.report();
+        let first_steps = report
+            .resource_limit_violations
+            .iter()
+            .map(|v| (v.resource, v.first_exceeded_step))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            first_steps,
+            vec![
+                (LabResource::Steps, 3),
+                (LabResource::LiveTasks, 1),
+                (LabResource::PendingObligations, 4),
+                (LabResource::TrackedMemoryBytes, 2),
+                (LabResource::VirtualTimeNanos, 4),
+            ]
+        );
+        assert_eq!(report.resource_usage.peak_live_tasks, 4);
+        assert_eq!(report.resource_usage.peak_tracked_memory_bytes, Some(4096));
+        assert!(
+            report
+                .invariant_violations
+                .iter()
+                .any(|v| v == "resource_limit:live_tasks:limit=2,peak=4,first_exceeded_step=1")
+        );
+        assert!(!report.lab_test_passed());
+        crate::test_complete!("resource_limits_report_step_where_each_limit_was_first_exceeded");
+    }
+
+    #[test]
+    fn resource_certificate_is_stable_across_same_seed_runs() {
+        init_test("resource_certificate_is_stable_across_same_seed_runs");
+        let run = |seed: u64| {
+            let mut runtime = LabRuntime::new(LabConfig::new(seed).worker_count(2));
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            for i in 0..5_u64 {
+                let (task_id, _handle) = runtime
+                    .state
+                    .create_task(root, Budget::INFINITE, async move {
+                        for _ in 0..i {
+                            futures_lite::future::yield_now().await;
+                        }
+                    })
+                    .expect("create task");
+                runtime.scheduler.lock().schedule(task_id, 0);
+            }
+            runtime.run_until_quiescent_with_report()
+        };
+
+        let first = run(34);
+        let second = run(34);
+        assert_eq!(first.resource_usage, second.resource_usage);
+        assert_eq!(first.resource_usage.peak_live_tasks, 5);
+        assert_eq!(first.resource_usage.steps, first.steps_total);
+        assert_eq!(first.resource_usage.peak_tracked_memory_bytes, None);
+        assert!(first.resource_limit_violations.is_empty());
+        crate::test_complete!("resource_certificate_is_stable_across_same_seed_runs");
+    }
+
     #[test]
     fn report_hydrates_quiescence_from_finalizers_and_obligations() {
         init_test("report_hydrates_quiescence_from_finalizers_and_obligations");
@@ -7005,6 +8495,10 @@ mod tests {
             format!("{}", AutoAdvanceTermination::StuckBailout),
             "stuck-bailout"
         );
+        assert_eq!(
+            format!("{}", AutoAdvanceTermination::BlockedByPauses),
+            "blocked-by-pauses"
+        );
     }
 
     #[test]
@@ -7014,6 +8508,7 @@ mod tests {
             AutoAdvanceTermination::Quiescent,
             AutoAdvanceTermination::StepLimitReached,
             AutoAdvanceTermination::StuckBailout,
+            AutoAdvanceTermination::BlockedByPauses,
         ];
         // Copy + Clone + Eq
         for &v in &variants {
@@ -7026,7 +8521,7 @@ mod tests {
         for &v in &variants {
             assert!(set.insert(v));
         }
-        assert_eq!(set.len(), 3);
+        assert_eq!(set.len(), 4);
         // Debug contains type name
         let dbg = format!("{:?}", AutoAdvanceTermination::StuckBailout);
         assert!(dbg.contains("StuckBailout"));
@@ -7221,6 +8716,62 @@ mod tests {
         crate::test_complete!("conformance_identical_seed_identical_trace");
     }
 
+    /// CONFORMANCE: Same seed produces byte-identical structured logs,
+    /// including trace and decision ids minted through `Cx`.
+    #[test]
+    fn conformance_identical_seed_identical_id_log() {
+        init_test("conformance_identical_seed_identical_id_log");
+
+        fn run_log(seed: u64) -> String {
+            let log = Arc::new(Mutex::new(String::new()));
+            let mut runtime = LabRuntime::new(LabConfig::new(seed).max_steps(1000));
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            for worker in 0..3_u32 {
+                let log = Arc::clone(&log);
+                let (task_id, _handle) = runtime
+                    .state
+                    .create_task(root, Budget::INFINITE, async move {
+                        for round in 0..4_u32 {
+                            let now = {
+                                let cx = crate::cx::Cx::current().expect("task Cx");
+                                let trace_id = cx.next_trace_id();
+                                let decision_id = cx.next_decision_id();
+                                let traceparent = crate::observability::W3CTraceContext::new_root()
+                                    .to_traceparent();
+                                let line = format!(
+                                    "{{\"worker\":{worker},\"round\":{round},\"trace_id\":\"{trace_id}\",\
+                                     \"decision_id\":\"{decision_id}\",\"traceparent\":\"{traceparent}\"}}\n"
+                                );
+                                log.lock().push_str(&line);
+                                cx.now()
+                            };
+                            crate::time::sleep(now, Duration::from_millis(5)).await;
+                        }
+                    })
+                    .expect("create task");
+                runtime.scheduler.lock().schedule(task_id, 0);
+            }
+            runtime.run_until_quiescent();
+            let out = log.lock().clone();
+            out
+        }
+
+        let first = run_log(42);
+        let second = run_log(42);
+        crate::assert_with_log!(
+            first.lines().count() == 12,
+            "log lines",
+            12,
+            first.lines().count()
+        );
+        crate::assert_with_log!(first == second, "byte-identical log", first, second);
+
+        let other = run_log(43);
+        crate::assert_with_log!(first != other, "seed changes ids", true, first != other);
+
+        crate::test_complete!("conformance_identical_seed_identical_id_log");
+    }
+
     /// CONFORMANCE: Virtual-time advances in same order across replays.
     ///
     /// Verifies that virtual time progression and auto-advancement
@@ -53,6 +53,13 @@
 //!   max_fault_events: 8
 //!   max_counterexample_events: 16
 //!
+//! limits:
+//!   max_steps: 120000
+//!   max_live_tasks: 256
+//!   max_pending_obligations: 64
+//!   max_tracked_memory_bytes: 8388608
+//!   max_virtual_time_ms: 30000
+//!
 //! expected_invariants:
 //!   - quiescence
 //!   - losers_drained
@@ -70,6 +77,50 @@
 //!   redacted: true
 //! ```
 //!
+//! # Connect faults
+//!
+//! `faults` may also be a mapping: the list above moves under `timeline`,
+//! and `connect` scripts DNS and TLS handshake outcomes per name and per
+//! endpoint (see [`ConnectFaults`]). The `connect` scripts land in
+//! [`Scenario::connect_faults`]; `faults` itself stays the timed list:
+//!
+//! ```yaml
+//! faults:
+//!   timeline: []
+//!   connect:
+//!     resolve:
+//!       api.internal: [{ kind: servfail }, { kind: slow, latency_ms: 800 }]
+//!     handshake:
+//!       "10.0.0.1:443": [{ kind: cert_verification }]
+//! ```
+//!
+//! # Topology
+//!
+//! `topology` declares named nodes and impaired links for multi-node runs
+//! (see [`Topology`]). Link faults and subtree partitions in `faults` address
+//! topology nodes by name, and every `flows` pair must be connected:
+//!
+//! ```yaml
+//! topology:
+//!   nodes: [spine, leaf1, leaf2, wan]
+//!   links:
+//!     - { a: spine, b: leaf1, delay_ms: 1 }
+//!     - { a: spine, b: leaf2, delay_ms: 1 }
+//!     - { a: spine, b: wan, delay_ms: 40, jitter_ms: 5, loss: 0.01, bandwidth: 1250000 }
+//!   flows:
+//!     - { from: leaf1, to: wan }
+//! faults:
+//!   - at_ms: 100
+//!     action: link_degrade
+//!     args: { a: spine, b: wan, loss: 0.2 }
+//!   - at_ms: 200
+//!     action: partition_subtree
+//!     args: { root: leaf2, parent: spine }
+//! ```
+//!
+//! [`Scenario::topology_network`] builds the matching [`TopologyNetwork`]
+//! with those faults scheduled at their virtual times.
+//!
 //! # Composability
 //!
 //! Scenarios may include other scenarios via `include`:
@@ -80,15 +131,40 @@
 //! ```
 //!
 //! Included fields are merged with the current file; the current file
-//! wins on conflict.
+//! wins on conflict.  Include paths are relative to the including file,
+//! and loaders must reject include cycles (see [`IncludeRef`]).
+//!
+//! # Ordering invariants
+//!
+//! `ordering` declares rules over markers that tasks record with
+//! `Cx::mark`; the runner checks them against the recorded trace and reports
+//! each broken rule as an invariant violation (see
+//! [`OrderingRule`]):
+//!
+//! ```yaml
+//! ordering:
+//!   - rule: happens_before
+//!     before: db_commit
+//!     after: cache_invalidate
+//!     key: key
+//! ```
 //!
 //! # Determinism
 //!
 //! All randomness is seeded via `lab.seed`.  Given the same YAML + the
 //! same runtime binary, execution is bit-identical.
 
-use serde::{Deserialize, Serialize};
+use crate::lab::network::{
+    ConnectFaults, LinkProfile, Topology, TopologyError, TopologyMutation, TopologyNetwork,
+};
+use crate::lab::oracle::OrderingRule;
+use crate::types::Time;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
 
 // ---------------------------------------------------------------------------
 // Top-level scenario
@@ -98,7 +174,12 @@ use std::collections::{BTreeMap, HashSet};
 pub const SCENARIO_SCHEMA_VERSION: u32 = 1;
 
 /// A complete FrankenLab test scenario.
+///
+/// The derived (de)serializers are inherent (`remote = "Self"`); the trait
+/// impls below wrap them to read and write the `faults` key, which carries
+/// both [`Scenario::faults`] and [`Scenario::connect_faults`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Scenario {
     /// Schema version (must be 1).
     #[serde(default = "default_schema_version")]
@@ -123,10 +204,19 @@ pub struct Scenario {
     #[serde(default)]
     pub network: NetworkSection,
 
+    /// Named multi-node topology.
+    #[serde(default, skip_serializing_if = "TopologySection::is_empty")]
+    pub topology: TopologySection,
+
     /// Timed fault injection events.
-    #[serde(default)]
+    #[serde(skip)]
     pub faults: Vec<FaultEvent>,
 
+    /// Scripted DNS and TLS handshake outcomes from `faults.connect`,
+    /// replayed by a [`ConnectSim`](crate::lab::network::ConnectSim).
+    #[serde(skip)]
+    pub connect_faults: ConnectFaults,
+
     /// Named participants (actors/tasks).
     #[serde(default)]
     pub participants: Vec<Participant>,
@@ -143,10 +233,18 @@ pub struct Scenario {
     #[serde(default)]
     pub resource_caps: ResourceCapsSection,
 
+    /// Hard runtime resource limits enforced against the run's resource certificate.
+    #[serde(default)]
+    pub limits: LimitsSection,
+
     /// Invariants the scenario expects the runner to enforce or report.
     #[serde(default = "default_expected_invariants")]
     pub expected_invariants: Vec<String>,
 
+    /// Ordering rules over `Cx::mark` markers, checked against the trace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ordering: Vec<OrderingRule>,
+
     /// Counterexample minimization settings.
     #[serde(default)]
     pub minimization: MinimizationSection,
@@ -202,12 +300,16 @@ impl Default for Scenario {
             lab: LabSection::default(),
             chaos: ChaosSection::default(),
             network: NetworkSection::default(),
+            topology: TopologySection::default(),
             faults: Vec::new(),
+            connect_faults: ConnectFaults::default(),
             participants: Vec::new(),
             oracles: default_oracles(),
             cancellation: None,
             resource_caps: ResourceCapsSection::default(),
+            limits: LimitsSection::default(),
             expected_invariants: default_expected_invariants(),
+            ordering: Vec::new(),
             minimization: MinimizationSection::default(),
             golden_projection: GoldenProjectionSection::default(),
             include: Vec::new(),
@@ -425,6 +527,93 @@ pub enum LatencySpec {
     },
 }
 
+// ---------------------------------------------------------------------------
+// Topology
+// ---------------------------------------------------------------------------
+
+/// Named nodes and impaired links for multi-node scenarios.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopologySection {
+    /// Node names.
+    #[serde(default)]
+    pub nodes: Vec<String>,
+
+    /// Bidirectional links between nodes.
+    #[serde(default)]
+    pub links: Vec<TopologyLinkSpec>,
+
+    /// Node pairs the scenario sends traffic between; each must be connected.
+    #[serde(default)]
+    pub flows: Vec<TopologyFlow>,
+}
+
+impl TopologySection {
+    /// Returns true if no nodes are declared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.links.is_empty() && self.flows.is_empty()
+    }
+
+    /// Builds the declared [`Topology`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid node or link declaration.
+    pub fn to_topology(&self) -> Result<Topology, TopologyError> {
+        let mut builder = Topology::builder();
+        for node in &self.nodes {
+            builder = builder.node(node.as_str());
+        }
+        for link in &self.links {
+            builder = builder.link(link.a.as_str(), link.b.as_str(), link.profile());
+        }
+        builder.build()
+    }
+}
+
+/// A link in the `topology` section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyLinkSpec {
+    /// First endpoint.
+    pub a: String,
+    /// Second endpoint.
+    pub b: String,
+    /// One-way delay in milliseconds.
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Maximum per-packet jitter in milliseconds.
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Per-hop loss probability (0.0-1.0).
+    #[serde(default)]
+    pub loss: f64,
+    /// Bandwidth limit (bytes/second); unlimited when absent.
+    #[serde(default)]
+    pub bandwidth: Option<u64>,
+}
+
+impl TopologyLinkSpec {
+    /// Returns the [`LinkProfile`] this link declares.
+    #[must_use]
+    pub const fn profile(&self) -> LinkProfile {
+        LinkProfile {
+            bandwidth: self.bandwidth,
+            delay: Duration::from_millis(self.delay_ms),
+            jitter: Duration::from_millis(self.jitter_ms),
+            loss: self.loss,
+        }
+    }
+}
+
+/// A traffic pair the scenario depends on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyFlow {
+    /// Sending node.
+    pub from: String,
+    /// Receiving node.
+    pub to: String,
+}
+
 // ---------------------------------------------------------------------------
 // Fault events
 // ---------------------------------------------------------------------------
@@ -469,6 +658,123 @@ pub enum FaultAction {
     ClockSkew,
     /// Reset clock skew to zero on a participant.
     ClockReset,
+    /// Replace the profile of a topology link; unset fields keep the
+    /// declared values.
+    LinkDegrade,
+    /// Restore a topology link to its declared profile.
+    LinkRestore,
+    /// Partition a topology subtree from every other node.
+    PartitionSubtree,
+    /// Heal a previously partitioned topology subtree.
+    HealSubtree,
+}
+
+/// On-disk form of the `faults` key: a bare list of timed events, or a
+/// mapping of `timeline` (that list) and `connect` scripts.
+#[derive(Default)]
+struct FaultsWire {
+    timeline: Vec<FaultEvent>,
+    connect: ConnectFaults,
+}
+
+#[derive(Deserialize)]
+struct FaultSections {
+    #[serde(default)]
+    timeline: Vec<FaultEvent>,
+    #[serde(default)]
+    connect: ConnectFaults,
+}
+
+impl<'de> Deserialize<'de> for FaultsWire {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FaultsVisitor;
+
+        impl<'de> Visitor<'de> for FaultsVisitor {
+            type Value = FaultsWire;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a list of fault events or a map of `timeline` and `connect`")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let timeline = Vec::<FaultEvent>::deserialize(SeqAccessDeserializer::new(seq))?;
+                Ok(FaultsWire {
+                    timeline,
+                    connect: ConnectFaults::default(),
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let sections = FaultSections::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(FaultsWire {
+                    timeline: sections.timeline,
+                    connect: sections.connect,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(FaultsVisitor)
+    }
+}
+
+/// Serializes a scenario's faults as the bare list unless it has connect
+/// scripts.
+struct FaultsOut<'a>(&'a Scenario);
+
+impl Serialize for FaultsOut<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let scenario = self.0;
+        if scenario.connect_faults.is_empty() {
+            return scenario.faults.serialize(serializer);
+        }
+        let mut section = serializer.serialize_struct("Faults", 2)?;
+        section.serialize_field("timeline", &scenario.faults)?;
+        section.serialize_field("connect", &scenario.connect_faults)?;
+        section.end()
+    }
+}
+
+/// Serializes every scenario field except `faults` via the derived code.
+struct ScenarioBody<'a>(&'a Scenario);
+
+impl Serialize for ScenarioBody<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Scenario::serialize(self.0, serializer)
+    }
+}
+
+#[derive(Serialize)]
+struct ScenarioOut<'a> {
+    #[serde(flatten)]
+    body: ScenarioBody<'a>,
+    faults: FaultsOut<'a>,
+}
+
+#[derive(Deserialize)]
+struct ScenarioIn {
+    #[serde(default)]
+    faults: FaultsWire,
+    #[serde(flatten, with = "Scenario")]
+    body: Scenario,
+}
+
+impl Serialize for Scenario {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ScenarioOut {
+            body: ScenarioBody(self),
+            faults: FaultsOut(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Scenario {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ScenarioIn { faults, mut body } = ScenarioIn::deserialize(deserializer)?;
+        body.faults = faults.timeline;
+        body.connect_faults = faults.connect;
+        Ok(body)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -549,6 +855,49 @@ pub struct ResourceCapsSection {
     pub max_counterexample_events: Option<usize>,
 }
 
+/// Hard resource limits checked against the lab run's resource certificate.
+///
+/// Exceeding a limit fails the run like an oracle violation and reports the
+/// scheduler step at which the limit was first exceeded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitsSection {
+    /// Maximum scheduler steps the scenario may take.
+    #[serde(default)]
+    pub max_steps: Option<u64>,
+
+    /// Maximum concurrently live tasks.
+    #[serde(default)]
+    pub max_live_tasks: Option<u64>,
+
+    /// Maximum concurrently pending obligations.
+    #[serde(default)]
+    pub max_pending_obligations: Option<u64>,
+
+    /// Maximum tracked buffer memory, in bytes.
+    #[serde(default)]
+    pub max_tracked_memory_bytes: Option<u64>,
+
+    /// Maximum total virtual time, in milliseconds.
+    #[serde(default)]
+    pub max_virtual_time_ms: Option<u64>,
+}
+
+impl LimitsSection {
+    /// Convert to the runtime's [`LabResourceLimits`](super::config::LabResourceLimits).
+    #[must_use]
+    pub fn to_resource_limits(&self) -> super::config::LabResourceLimits {
+        super::config::LabResourceLimits {
+            max_steps: self.max_steps,
+            max_live_tasks: self.max_live_tasks,
+            max_pending_obligations: self.max_pending_obligations,
+            max_tracked_memory_bytes: self.max_tracked_memory_bytes,
+            max_virtual_time_nanos: self
+                .max_virtual_time_ms
+                .map(|ms| ms.saturating_mul(1_000_000)),
+        }
+    }
+}
+
 /// Counterexample minimization policy for failing scenario runs.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MinimizationSection {
@@ -607,12 +956,77 @@ impl Default for GoldenProjectionSection {
 // ---------------------------------------------------------------------------
 
 /// Reference to an included scenario file.
+///
+/// The path is resolved relative to the directory of the including file.
+/// Loaders expand includes depth-first, in list order: later includes
+/// override earlier ones, and the including file overrides all of them.
+/// Mappings merge key by key; any other value (including sequences such
+/// as `faults`) is replaced wholesale.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IncludeRef {
     /// Relative path to the included YAML.
     pub path: String,
 }
 
+impl IncludeRef {
+    /// Returns why this include path is unsafe to resolve, or `None` if it
+    /// is acceptable.
+    ///
+    /// Include paths must be relative, free of `..` components and control
+    /// characters, limited to `[A-Za-z0-9._/-]`, at most 255 bytes, and end
+    /// in `.yaml` or `.yml`.
+    #[must_use]
+    pub fn path_error(&self) -> Option<&'static str> {
+        let path = self.path.as_str();
+
+        // Security: Reject empty paths
+        if path.is_empty() {
+            return Some("include path must not be empty");
+        }
+
+        // Security: Reject absolute paths
+        if path.starts_with('/') || path.starts_with('\\') {
+            return Some("include path must not be absolute (no leading / or \\)");
+        }
+
+        // Security: Reject path traversal attempts
+        if path.contains("..") {
+            return Some("include path must not contain '..' (path traversal attack)");
+        }
+
+        // Security: Reject paths with null bytes or control characters
+        if path.chars().any(|c| c.is_control() || c == '\0') {
+            return Some("include path must not contain control characters or null bytes");
+        }
+
+        // Security: Restrict to reasonable filename characters
+        let allowed_chars = |c: char| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '/');
+        if !path.chars().all(allowed_chars) {
+            return Some(
+                "include path contains invalid characters (only alphanumeric, '.', '_', '-', '/' allowed)",
+            );
+        }
+
+        // Security: Reject excessively long paths
+        if path.len() > 255 {
+            return Some("include path too long (maximum 255 characters)");
+        }
+
+        // Security: Require .yaml or .yml extension
+        let has_yaml_extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml")
+            });
+        if has_yaml_extension {
+            None
+        } else {
+            Some("include path must end with .yaml or .yml extension")
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Validation
 // ---------------------------------------------------------------------------
@@ -644,11 +1058,14 @@ impl Scenario {
         self.validate_header(&mut errors);
         self.validate_chaos(&mut errors);
         self.validate_network(&mut errors);
+        self.validate_topology(&mut errors);
         self.validate_faults(&mut errors);
         self.validate_participants(&mut errors);
         self.validate_cancellation(&mut errors);
         self.validate_resource_caps(&mut errors);
+        self.validate_limits(&mut errors);
         self.validate_expected_invariants(&mut errors);
+        self.validate_ordering(&mut errors);
         self.validate_minimization(&mut errors);
         self.validate_golden_projection(&mut errors);
         self.validate_includes(&mut errors);
@@ -784,9 +1201,61 @@ impl Scenario {
         }
     }
 
+    fn validate_topology(&self, errors: &mut Vec<ValidationError>) {
+        if self.topology.is_empty() {
+            for (index, fault) in self.faults.iter().enumerate() {
+                if is_topology_action(&fault.action) {
+                    errors.push(ValidationError {
+                        field: format!("faults[{index}].action"),
+                        message: "topology fault requires a `topology` section".into(),
+                    });
+                }
+            }
+            return;
+        }
+
+        let topology = match self.topology.to_topology() {
+            Ok(topology) => topology,
+            Err(err) => {
+                errors.push(ValidationError {
+                    field: "topology".into(),
+                    message: err.to_string(),
+                });
+                return;
+            }
+        };
+
+        for (index, flow) in self.topology.flows.iter().enumerate() {
+            if let Err(err) = topology.route(&flow.from, &flow.to) {
+                errors.push(ValidationError {
+                    field: format!("topology.flows[{index}]"),
+                    message: err.to_string(),
+                });
+            }
+        }
+
+        // Scheduling resolves every name without applying anything.
+        let mut scratch = TopologyNetwork::new(topology.clone(), self.lab.seed);
+        for (index, fault) in self.faults.iter().enumerate() {
+            let checked = topology_mutation(&topology, fault).and_then(|mutation| {
+                mutation.map_or(Ok(()), |mutation| scratch.schedule(Time::ZERO, mutation))
+            });
+            if let Err(err) = checked {
+                errors.push(ValidationError {
+                    field: format!("faults[{index}]"),
+                    message: err.to_string(),
+                });
+            }
+        }
+    }
+
     fn validate_faults(&self, errors: &mut Vec<ValidationError>) {
-        let participant_names: HashSet<&str> =
+        let mut participant_names: HashSet<&str> =
             self.participants.iter().map(|p| p.name.as_str()).collect();
+        // Topology nodes are valid partition endpoints too.
+        if !participant_names.is_empty() {
+            participant_names.extend(self.topology.nodes.iter().map(String::as_str));
+        }
 
         for (index, fault) in self.faults.iter().enumerate() {
             Self::validate_fault_args(index, fault, &participant_names, errors);
@@ -803,6 +1272,15 @@ impl Scenario {
                 });
             }
         }
+
+        for name in self.connect_faults.resolve.keys() {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                errors.push(ValidationError {
+                    field: "faults.connect.resolve".into(),
+                    message: format!("invalid host name `{name}`"),
+                });
+            }
+        }
     }
 
     fn validate_fault_args(
@@ -905,6 +1383,14 @@ impl Scenario {
                 }
                 Self::required_fault_i64_arg(fault_index, &fault.args, "skew_ms", errors);
             }
+            FaultAction::LinkDegrade | FaultAction::LinkRestore => {
+                Self::required_fault_string_arg(fault_index, &fault.args, "a", errors);
+                Self::required_fault_string_arg(fault_index, &fault.args, "b", errors);
+            }
+            FaultAction::PartitionSubtree | FaultAction::HealSubtree => {
+                Self::required_fault_string_arg(fault_index, &fault.args, "root", errors);
+                Self::required_fault_string_arg(fault_index, &fault.args, "parent", errors);
+            }
         }
     }
 
@@ -1064,6 +1550,26 @@ impl Scenario {
         }
     }
 
+    fn validate_limits(&self, errors: &mut Vec<ValidationError>) {
+        if self.limits.max_steps == Some(0) {
+            errors.push(ValidationError {
+                field: "limits.max_steps".into(),
+                message: "max_steps must be >= 1 when set".into(),
+            });
+        }
+
+        if let (Some(limit), Some(lab_max)) = (self.limits.max_steps, self.lab.max_steps)
+            && limit >= lab_max
+        {
+            errors.push(ValidationError {
+                field: "limits.max_steps".into(),
+                message: format!(
+                    "max_steps {limit} can never be exceeded because lab.max_steps stops the run at {lab_max}"
+                ),
+            });
+        }
+    }
+
     fn validate_expected_invariants(&self, errors: &mut Vec<ValidationError>) {
         if self.expected_invariants.is_empty() {
             errors.push(ValidationError {
@@ -1100,6 +1606,17 @@ impl Scenario {
         }
     }
 
+    fn validate_ordering(&self, errors: &mut Vec<ValidationError>) {
+        for (index, rule) in self.ordering.iter().enumerate() {
+            if let Some(message) = rule.validate() {
+                errors.push(ValidationError {
+                    field: format!("ordering[{index}]"),
+                    message: message.into(),
+                });
+            }
+        }
+    }
+
     fn validate_minimization(&self, errors: &mut Vec<ValidationError>) {
         if self.minimization.enabled {
             match self.minimization.max_evaluations {
@@ -1140,75 +1657,10 @@ impl Scenario {
 
     fn validate_includes(&self, errors: &mut Vec<ValidationError>) {
         for (index, include) in self.include.iter().enumerate() {
-            let field = format!("include[{index}].path");
-
-            // Security: Reject empty paths
-            if include.path.is_empty() {
+            if let Some(message) = include.path_error() {
                 errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path must not be empty".into(),
-                });
-                continue;
-            }
-
-            // Security: Reject absolute paths
-            if include.path.starts_with('/') || include.path.starts_with('\\') {
-                errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path must not be absolute (no leading / or \\)".into(),
-                });
-                continue;
-            }
-
-            // Security: Reject path traversal attempts
-            if include.path.contains("..") {
-                errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path must not contain '..' (path traversal attack)".into(),
-                });
-                continue;
-            }
-
-            // Security: Reject paths with null bytes or control characters
-            if include.path.chars().any(|c| c.is_control() || c == '\0') {
-                errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path must not contain control characters or null bytes"
-                        .into(),
-                });
-                continue;
-            }
-
-            // Security: Restrict to reasonable filename characters
-            let allowed_chars = |c: char| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '/');
-            if !include.path.chars().all(allowed_chars) {
-                errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path contains invalid characters (only alphanumeric, '.', '_', '-', '/' allowed)".into(),
-                });
-                continue;
-            }
-
-            // Security: Reject excessively long paths
-            if include.path.len() > 255 {
-                errors.push(ValidationError {
-                    field: field.clone(),
-                    message: "include path too long (maximum 255 characters)".into(),
-                });
-                continue;
-            }
-
-            // Security: Require .yaml or .yml extension
-            let has_yaml_extension = std::path::Path::new(&include.path)
-                .extension()
-                .and_then(|extension| extension.to_str())
-                .is_some_and(|extension| {
-                    extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml")
-                });
-            if !has_yaml_extension {
-                errors.push(ValidationError {
-                    field,
-                    message: "include path must end with .yaml or .yml extension".into(),
+                    field: format!("include[{index}].path"),
+                    message: message.into(),
                 });
             }
         }
@@ -1267,7 +1719,32 @@ impl Scenario {
             config = config.with_default_replay_recording();
         }
 
-        config
+        config.with_resource_limits(self.limits.to_resource_limits())
+    }
+
+    /// Builds a [`TopologyNetwork`] for the `topology` section, seeded with
+    /// `lab.seed`, with every topology fault in the timeline scheduled at
+    /// its `at_ms`.
+    ///
+    /// `partition` and `heal` events whose endpoints are both topology nodes
+    /// become node-to-node partitions. Returns `Ok(None)` when the scenario
+    /// declares no topology.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid topology declaration or fault reference.
+    pub fn topology_network(&self) -> Result<Option<TopologyNetwork>, TopologyError> {
+        if self.topology.is_empty() {
+            return Ok(None);
+        }
+        let topology = self.topology.to_topology()?;
+        let mut network = TopologyNetwork::new(topology.clone(), self.lab.seed);
+        for fault in &self.faults {
+            if let Some(mutation) = topology_mutation(&topology, fault)? {
+                network.schedule(Time::from_millis(fault.at_ms), mutation)?;
+            }
+        }
+        Ok(Some(network))
     }
 
     /// Parse a scenario from a JSON string.
@@ -1289,6 +1766,79 @@ impl Scenario {
     }
 }
 
+const fn is_topology_action(action: &FaultAction) -> bool {
+    matches!(
+        action,
+        FaultAction::LinkDegrade
+            | FaultAction::LinkRestore
+            | FaultAction::PartitionSubtree
+            | FaultAction::HealSubtree
+    )
+}
+
+/// Maps a fault event onto the topology, or `None` if it does not address it.
+fn topology_mutation(
+    topology: &Topology,
+    fault: &FaultEvent,
+) -> Result<Option<TopologyMutation>, TopologyError> {
+    let arg = |key: &str| {
+        fault
+            .args
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let mutation = match fault.action {
+        FaultAction::Partition | FaultAction::Heal => {
+            let (from, to) = (arg("from"), arg("to"));
+            if !topology.contains(&from) || !topology.contains(&to) {
+                return Ok(None);
+            }
+            let (side_a, side_b) = (vec![from], vec![to]);
+            if matches!(fault.action, FaultAction::Partition) {
+                TopologyMutation::Partition { side_a, side_b }
+            } else {
+                TopologyMutation::Heal { side_a, side_b }
+            }
+        }
+        FaultAction::LinkDegrade => {
+            let (a, b) = (arg("a"), arg("b"));
+            let Some(&declared) = topology.link_profile(&a, &b) else {
+                return Err(TopologyError::UnknownLink { a, b });
+            };
+            let mut profile = declared;
+            let number = |key: &str| fault.args.get(key).and_then(serde_json::Value::as_u64);
+            if let Some(delay_ms) = number("delay_ms") {
+                profile.delay = Duration::from_millis(delay_ms);
+            }
+            if let Some(jitter_ms) = number("jitter_ms") {
+                profile.jitter = Duration::from_millis(jitter_ms);
+            }
+            if let Some(bandwidth) = number("bandwidth") {
+                profile.bandwidth = Some(bandwidth);
+            }
+            if let Some(loss) = fault.args.get("loss").and_then(serde_json::Value::as_f64) {
+                profile.loss = loss;
+            }
+            TopologyMutation::DegradeLink { a, b, profile }
+        }
+        FaultAction::LinkRestore => TopologyMutation::RestoreLink {
+            a: arg("a"),
+            b: arg("b"),
+        },
+        FaultAction::PartitionSubtree => {
+            TopologyMutation::partition_subtree(topology, &arg("root"), &arg("parent"))?
+        }
+        FaultAction::HealSubtree => {
+            TopologyMutation::heal_subtree(topology, &arg("root"), &arg("parent"))?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(mutation))
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -1304,6 +1854,7 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::bytes::Bytes;
 
     fn minimal_json() -> &'static str {
         r#"{
@@ -1664,6 +2215,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_connect_faults_section() {
+        use crate::lab::network::{HandshakeFault, ResolveFault};
+
+        let json = r#"{
+            "id": "x",
+            "faults": {
+                "timeline": [{"at_ms": 100, "action": "partition", "args": {"from": "a", "to": "b"}}],
+                "connect": {
+                    "resolve": {"api.internal": [{"kind": "servfail", "latency_ms": 5}, {"kind": "timeout"}]},
+                    "handshake": {"10.0.0.1:443": [{"kind": "version_mismatch"}]}
+                }
+            }
+        }"#;
+        let s = Scenario::from_json(json).unwrap();
+        assert_eq!(s.faults.len(), 1);
+        assert_eq!(
+            s.connect_faults.resolve["api.internal"],
+            [
+                ResolveFault::ServFail { latency_ms: 5 },
+                ResolveFault::Timeout
+            ]
+        );
+        let endpoint: std::net::SocketAddr = "10.0.0.1:443".parse().unwrap();
+        assert_eq!(
+            s.connect_faults.handshake[&endpoint],
+            [HandshakeFault::VersionMismatch { latency_ms: 0 }]
+        );
+
+        let reparsed = Scenario::from_json(&s.to_json().unwrap()).unwrap();
+        assert_eq!(reparsed.connect_faults, s.connect_faults);
+        assert_eq!(reparsed.faults.len(), 1);
+
+        // Without connect faults the section keeps the bare-list form.
+        let mut legacy = s;
+        legacy.connect_faults = ConnectFaults::default();
+        let value: serde_json::Value = serde_json::from_str(&legacy.to_json().unwrap()).unwrap();
+        assert!(value["faults"].is_array());
+
+        legacy.connect_faults.resolve.insert(String::new(), Vec::new());
+        assert!(
+            legacy
+                .validate()
+                .iter()
+                .any(|e| e.field == "faults.connect.resolve")
+        );
+    }
+
     #[test]
     fn parse_participants() {
         let json = r#"{
@@ -1821,6 +2420,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn limits_parse_and_reject_unenforceable_step_limit() {
+        let json = r#"{
+            "id": "x",
+            "lab": { "max_steps": 1000 },
+            "limits": {
+                "max_steps": 1000,
+                "max_pending_obligations": 64,
+                "max_tracked_memory_bytes": 8388608,
+                "max_virtual_time_ms": 30
+            }
+        }"#;
+
+        let s: Scenario = serde_json::from_str(json).unwrap();
+        let limits = s.to_lab_config().resource_limits;
+        assert_eq!(limits.max_pending_obligations, Some(64));
+        assert_eq!(limits.max_tracked_memory_bytes, Some(8_388_608));
+        assert_eq!(limits.max_virtual_time_nanos, Some(30_000_000));
+        assert_eq!(limits.max_live_tasks, None);
+        assert!(s.validate().iter().any(|e| e.field == "limits.max_steps"));
+    }
+
     #[test]
     fn validate_expected_invariants_fail_closed() {
         let json = r#"{
@@ -1845,6 +2466,128 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ordering_rules_parse_and_validate() {
+        let json = r#"{
+            "id": "x",
+            "ordering": [
+                { "rule": "happens_before", "before": "commit", "after": "publish", "key": "id" },
+                { "rule": "mutual_exclusion", "enter": "lock", "exit": "lock" }
+            ]
+        }"#;
+
+        let s: Scenario = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            s.ordering[0],
+            OrderingRule::happens_before("commit", "publish").keyed_by("id")
+        );
+        let errors = s.validate();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "ordering[1]" && e.message.contains("distinct"))
+        );
+        assert!(!errors.iter().any(|e| e.field == "ordering[0]"));
+    }
+
+    #[test]
+    fn topology_section_validates_links_flows_and_fault_refs() {
+        let json = r#"{
+            "id": "x",
+            "topology": {
+                "nodes": ["spine", "leaf", "island"],
+                "links": [{ "a": "spine", "b": "leaf", "delay_ms": 2, "loss": 0.1 }],
+                "flows": [{ "from": "leaf", "to": "island" }]
+            },
+            "faults": [
+                { "at_ms": 10, "action": "link_degrade", "args": { "a": "leaf", "b": "island" } },
+                { "at_ms": 20, "action": "partition_subtree", "args": { "root": "leaf" } }
+            ]
+        }"#;
+
+        let s = Scenario::from_json(json).unwrap();
+        let profile = s.topology.links[0].profile();
+        assert_eq!(profile.delay, Duration::from_millis(2));
+        assert!((profile.loss - 0.1).abs() < f64::EPSILON);
+
+        let errors = s.validate();
+        assert!(errors.iter().any(|e| e.field == "topology.flows[0]"));
+        assert!(errors.iter().any(|e| e.field == "faults[0]"));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "faults[1].args.parent" && e.message.contains("required"))
+        );
+
+        let json = r#"{
+            "id": "x",
+            "topology": { "nodes": ["a"], "links": [{ "a": "a", "b": "ghost" }] }
+        }"#;
+        let errors = Scenario::from_json(json).unwrap().validate();
+        assert!(errors.iter().any(|e| e.field == "topology" && e.message.contains("ghost")));
+
+        let json = r#"{
+            "id": "x",
+            "faults": [{ "at_ms": 1, "action": "link_restore", "args": { "a": "a", "b": "b" } }]
+        }"#;
+        let errors = Scenario::from_json(json).unwrap().validate();
+        assert!(errors.iter().any(|e| e.field == "faults[0].action"));
+    }
+
+    #[test]
+    fn topology_network_schedules_timeline_faults() {
+        let json = r#"{
+            "id": "x",
+            "topology": {
+                "nodes": ["a", "b", "c"],
+                "links": [
+                    { "a": "a", "b": "b", "delay_ms": 5 },
+                    { "a": "b", "b": "c", "delay_ms": 5 }
+                ],
+                "flows": [{ "from": "a", "to": "c" }]
+            },
+            "faults": [
+                {
+                    "at_ms": 100,
+                    "action": "link_degrade",
+                    "args": { "a": "a", "b": "b", "delay_ms": 50 }
+                },
+                {
+                    "at_ms": 200,
+                    "action": "partition_subtree",
+                    "args": { "root": "c", "parent": "b" }
+                }
+            ]
+        }"#;
+
+        let s = Scenario::from_json(json).unwrap();
+        assert!(s.validate().is_empty(), "{:?}", s.validate());
+        assert!(Scenario::default().topology_network().unwrap().is_none());
+
+        let mut network = s.topology_network().unwrap().unwrap();
+        network.run_until(Time::from_millis(99));
+        assert_eq!(
+            network.current_profile("a", "b").unwrap().delay,
+            Duration::from_millis(5)
+        );
+        network.run_until(Time::from_millis(100));
+        assert_eq!(
+            network.current_profile("a", "b").unwrap().delay,
+            Duration::from_millis(50)
+        );
+
+        network.send("a", "c", Bytes::from_static(b"slow")).unwrap();
+        network.run_until(Time::from_millis(199));
+        let inbox = network.take_inbox("c").unwrap();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].received_at, Time::from_millis(155));
+
+        network.run_until(Time::from_millis(200));
+        network.send("a", "c", Bytes::from_static(b"cut")).unwrap();
+        network.run_until_idle();
+        assert!(network.take_inbox("c").unwrap().is_empty());
+    }
+
     #[test]
     fn validate_minimization_requires_positive_budget_when_enabled() {
         let json = r#"{
@@ -4,6 +4,7 @@
 //! [`LabRuntime`](super::runtime::LabRuntime) execution, providing:
 //!
 //! - Timed fault injection based on scenario fault events
+//! - Connect fault replay through a [`ConnectSim`](super::network::ConnectSim)
 //! - Oracle filtering (only check oracles listed in the scenario)
 //! - Seed exploration (run the same scenario across multiple seeds)
 //! - Replay validation (run twice, verify identical trace certificates)
@@ -23,8 +24,12 @@
 
 use super::config::LabConfig;
 use super::dual_run::{DualRunScenarioIdentity, ReplayMetadata, SeedLineageRecord};
-use super::oracle::{OracleRegistry, OracleRegistryError, OracleReport};
-use super::runtime::{LabRunReport, LabRuntime};
+use super::network::{
+    ConnectSim, ConnectSimConfig, ConnectTraceEvent, ConnectTraceKind, HandshakeOutcome,
+    ResolveOutcome,
+};
+use super::oracle::{OracleRegistry, OracleRegistryError, OracleReport, OrderingOracle};
+use super::runtime::{LabResourceUsage, LabRunReport, LabRuntime};
 use super::scenario::{FaultAction, FaultEvent, Scenario, ValidationError};
 use crate::trace::replay::ReplayTrace;
 use crate::types::Time;
@@ -139,6 +144,8 @@ pub struct TraceCertificateSnapshot {
     pub steps: u64,
     /// Trace fingerprint (Foata equivalence class).
     pub trace_fingerprint: u64,
+    /// Measured resource peaks (resource certificate).
+    pub resources: LabResourceUsage,
 }
 
 /// Structured record for a scenario fault injection.
@@ -275,7 +282,11 @@ impl FaultEffectSummary {
             | FaultAction::HostCrash
             | FaultAction::HostRestart
             | FaultAction::ClockSkew
-            | FaultAction::ClockReset => {}
+            | FaultAction::ClockReset
+            | FaultAction::LinkDegrade
+            | FaultAction::LinkRestore
+            | FaultAction::PartitionSubtree
+            | FaultAction::HealSubtree => {}
         }
     }
 
@@ -368,6 +379,8 @@ pub struct ScenarioRunResult {
     pub fault_log: Vec<FaultInjectionLogEntry>,
     /// Deterministic summary of effects applied by fault injection.
     pub fault_effect_summary: FaultEffectSummary,
+    /// Lookups and handshakes replayed from the scenario's connect faults.
+    pub connect_trace: Vec<ConnectTraceEvent>,
     /// Minimized counterexample packet when bounded fault effects remain unresolved.
     pub minimized_counterexample: Option<MinimizedCounterexamplePacket>,
     /// Replay trace, if recording was enabled.
@@ -408,6 +421,7 @@ impl ScenarioRunResult {
             "faults_injected": self.faults_injected,
             "fault_log": self.fault_log.iter().map(FaultInjectionLogEntry::to_json).collect::<Vec<_>>(),
             "fault_effect_summary": self.fault_effect_summary.to_json(),
+            "connect_trace": self.connect_trace.iter().map(connect_trace_json).collect::<Vec<_>>(),
             "minimized_counterexample": self
                 .minimized_counterexample
                 .as_ref()
@@ -416,7 +430,14 @@ impl ScenarioRunResult {
                 "event_hash": self.certificate.event_hash,
                 "schedule_hash": self.certificate.schedule_hash,
                 "trace_fingerprint": self.certificate.trace_fingerprint,
+                "resources": self.certificate.resources.to_json(),
             },
+            "resource_limit_violations": self
+                .lab_report
+                .resource_limit_violations
+                .iter()
+                .map(super::runtime::LabResourceLimitViolation::to_json)
+                .collect::<Vec<_>>(),
             "oracle_report": self.oracle_report.to_json(),
             "invariant_violations": self.lab_report.invariant_violations,
             "replay_metadata": &self.replay_metadata,
@@ -425,6 +446,42 @@ impl ScenarioRunResult {
     }
 }
 
+fn connect_trace_json(event: &ConnectTraceEvent) -> serde_json::Value {
+    use serde_json::json;
+    match &event.kind {
+        ConnectTraceKind::Resolve { name, outcome } => {
+            let outcome = match outcome {
+                ResolveOutcome::Answer(addrs) => {
+                    json!({ "answer": addrs.iter().map(ToString::to_string).collect::<Vec<_>>() })
+                }
+                ResolveOutcome::NxDomain => json!("nx_domain"),
+                ResolveOutcome::ServFail => json!("servfail"),
+                ResolveOutcome::Timeout => json!("timeout"),
+            };
+            json!({
+                "time_ns": event.time.as_nanos(),
+                "latency_ms": event.latency_ms,
+                "resolve": name,
+                "outcome": outcome,
+            })
+        }
+        ConnectTraceKind::Handshake { endpoint, outcome } => {
+            let outcome = match outcome {
+                HandshakeOutcome::Established => "established",
+                HandshakeOutcome::CertVerification => "cert_verification",
+                HandshakeOutcome::Timeout => "timeout",
+                HandshakeOutcome::VersionMismatch => "version_mismatch",
+            };
+            json!({
+                "time_ns": event.time.as_nanos(),
+                "latency_ms": event.latency_ms,
+                "handshake": endpoint.to_string(),
+                "outcome": outcome,
+            })
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Filtered oracle report
 // ---------------------------------------------------------------------------
@@ -601,6 +658,8 @@ pub struct ScenarioExplorationResult {
     pub runs: Vec<ExplorationRunSummary>,
     /// First failing seed, if any.
     pub first_failure_seed: Option<u64>,
+    /// Worst-case resource peaks across every explored seed.
+    pub worst_case_resources: LabResourceUsage,
 }
 
 impl ScenarioExplorationResult {
@@ -621,6 +680,7 @@ impl ScenarioExplorationResult {
             "failed": self.failed,
             "unique_fingerprints": self.unique_fingerprints,
             "first_failure_seed": self.first_failure_seed,
+            "worst_case_resources": self.worst_case_resources.to_json(),
             "runs": self.runs.iter().map(ExplorationRunSummary::to_json).collect::<Vec<_>>(),
         })
     }
@@ -639,6 +699,8 @@ pub struct ExplorationRunSummary {
     pub fingerprint: u64,
     /// Failure descriptions, if any.
     pub failures: Vec<String>,
+    /// Measured resource peaks for this seed.
+    pub resources: LabResourceUsage,
 }
 
 impl ExplorationRunSummary {
@@ -652,6 +714,7 @@ impl ExplorationRunSummary {
             "steps": self.steps,
             "fingerprint": self.fingerprint,
             "failures": self.failures,
+            "resources": self.resources.to_json(),
         })
     }
 }
@@ -815,6 +878,10 @@ impl ScenarioRunner {
                 FaultAction::HostRestart => "host_restart",
                 FaultAction::ClockSkew => "clock_skew",
                 FaultAction::ClockReset => "clock_reset",
+                FaultAction::LinkDegrade => "link_degrade",
+                FaultAction::LinkRestore => "link_restore",
+                FaultAction::PartitionSubtree => "partition_subtree",
+                FaultAction::HealSubtree => "heal_subtree",
             };
             let args_summary = Self::fault_args_summary(&fault.args);
             let trace_message = format!("fault:{action_name}:{args_summary}");
@@ -835,6 +902,30 @@ impl ScenarioRunner {
         (fault_log, fault_effect_summary)
     }
 
+    /// Replay the scenario's connect faults through a [`ConnectSim`].
+    ///
+    /// Each scripted lookup and handshake is performed once, names before
+    /// endpoints and both in sorted order, on a simulator seeded with the
+    /// run's seed. Returns the simulator's trace.
+    fn replay_connect_faults(scenario: &Scenario, seed: u64) -> Vec<ConnectTraceEvent> {
+        let faults = &scenario.connect_faults;
+        if faults.is_empty() {
+            return Vec::new();
+        }
+        let mut sim = ConnectSim::new(ConnectSimConfig::default().with_seed(seed), faults);
+        for (name, script) in &faults.resolve {
+            for _ in script {
+                let _ = sim.resolve(name);
+            }
+        }
+        for (endpoint, script) in &faults.handshake {
+            for _ in script {
+                let _ = sim.handshake(*endpoint);
+            }
+        }
+        sim.trace().to_vec()
+    }
+
     /// Summarize fault args for trace events.
     fn fault_args_summary(args: &BTreeMap<String, serde_json::Value>) -> String {
         let mut summary = String::new();
@@ -914,6 +1005,19 @@ impl ScenarioRunner {
         })
     }
 
+    /// Append the scenario's broken ordering rules to the report's violations.
+    fn check_ordering(scenario: &Scenario, runtime: &LabRuntime, report: &mut LabRunReport) {
+        if scenario.ordering.is_empty() {
+            return;
+        }
+        let oracle = OrderingOracle::from_rules(scenario.ordering.iter().cloned());
+        if let Err(violations) = oracle.check(&runtime.trace().snapshot()) {
+            report
+                .invariant_violations
+                .extend(violations.iter().map(ToString::to_string));
+        }
+    }
+
     /// Build a certificate snapshot from a lab report.
     fn certificate_snapshot(report: &LabRunReport) -> TraceCertificateSnapshot {
         TraceCertificateSnapshot {
@@ -921,6 +1025,7 @@ impl ScenarioRunner {
             schedule_hash: report.trace_certificate.schedule_hash,
             steps: report.steps_total,
             trace_fingerprint: report.trace_fingerprint,
+            resources: report.resource_usage,
         }
     }
 
@@ -959,9 +1064,11 @@ impl ScenarioRunner {
         let faults_injected = fault_log.len();
         let minimized_counterexample =
             Self::minimized_counterexample_for(scenario, &fault_log, &fault_effect_summary);
+        let connect_trace = Self::replay_connect_faults(scenario, effective_seed);
         runtime.run_until_quiescent();
 
-        let lab_report = runtime.report();
+        let mut lab_report = runtime.report();
+        Self::check_ordering(scenario, &runtime, &mut lab_report);
         let certificate = Self::certificate_snapshot(&lab_report);
         let replay_metadata = Self::replay_metadata_for_run(identity, &lab_report);
         let seed_lineage = identity.seed_lineage();
@@ -980,6 +1087,7 @@ impl ScenarioRunner {
             faults_injected,
             fault_log,
             fault_effect_summary,
+            connect_trace,
             minimized_counterexample,
             replay_trace,
             certificate,
@@ -1016,24 +1124,28 @@ impl ScenarioRunner {
         let minimized_counterexample =
             Self::minimized_counterexample_for(scenario, &fault_log, &fault_effect_summary);
 
-        // 4. Run to quiescence after all faults
+        // 4. Replay connect faults on their own simulated clock
+        let connect_trace = Self::replay_connect_faults(scenario, effective_seed);
+
+        // 5. Run to quiescence after all faults
         runtime.run_until_quiescent();
 
-        // 5. Collect report
-        let lab_report = runtime.report();
+        // 6. Collect report, including user-declared ordering rules
+        let mut lab_report = runtime.report();
+        Self::check_ordering(scenario, &runtime, &mut lab_report);
         let certificate = Self::certificate_snapshot(&lab_report);
         let identity = Self::scenario_identity(scenario, seed_override);
         let replay_metadata = Self::replay_metadata_for_run(&identity, &lab_report);
         let seed_lineage = identity.seed_lineage();
 
-        // 6. Filter oracle results
+        // 7. Filter oracle results
         let oracle_report = FilteredOracleReport::from_full(
             lab_report.oracle_report.clone(),
             &scenario.oracles,
             &runtime.config().oracle_selection,
         );
 
-        // 7. Extract replay trace
+        // 8. Extract replay trace
         let replay_trace = runtime.finish_replay_trace();
 
         Ok(ScenarioRunResult {
@@ -1044,6 +1156,7 @@ impl ScenarioRunner {
             faults_injected,
             fault_log,
             fault_effect_summary,
+            connect_trace,
             minimized_counterexample,
             replay_trace,
             certificate,
@@ -1076,6 +1189,7 @@ impl ScenarioRunner {
         let mut runs = Vec::with_capacity(count);
         let mut fingerprint_set = std::collections::HashSet::new();
         let mut first_failure_seed = None;
+        let mut worst_case_resources = LabResourceUsage::default();
 
         for i in 0..count {
             let seed = seed_start.wrapping_add(i as u64);
@@ -1083,6 +1197,7 @@ impl ScenarioRunner {
             let result = Self::run_with_seed(scenario, Some(seed))?;
 
             fingerprint_set.insert(result.certificate.trace_fingerprint);
+            worst_case_resources = worst_case_resources.worst_case(&result.certificate.resources);
 
             let passed = result.passed();
             let failures: Vec<String> = if passed {
@@ -1118,6 +1233,7 @@ impl ScenarioRunner {
                 steps: result.lab_report.steps_total,
                 fingerprint: result.certificate.trace_fingerprint,
                 failures,
+                resources: result.certificate.resources,
             });
         }
 
@@ -1132,6 +1248,7 @@ impl ScenarioRunner {
             unique_fingerprints: fingerprint_set.len(),
             runs,
             first_failure_seed,
+            worst_case_resources,
         })
     }
 
@@ -1172,9 +1289,10 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::lab::runtime::LabResource;
     use crate::lab::scenario::{
-        ChaosSection, FaultAction, FaultEvent, LabSection, MinimizationSection, NetworkSection,
-        Scenario,
+        ChaosSection, FaultAction, FaultEvent, LabSection, LimitsSection, MinimizationSection,
+        NetworkSection, Scenario,
     };
     use std::collections::BTreeMap;
 
@@ -1284,6 +1402,57 @@ mod tests {
         crate::test_complete!("run_with_faults");
     }
 
+    #[test]
+    fn run_replays_connect_faults_end_to_end() {
+        init_test("run_replays_connect_faults_end_to_end");
+        let yaml = r#"
+id: connect-faults-e2e
+lab:
+  seed: 7
+faults:
+  timeline:
+    - { at_ms: 10, action: partition, args: { from: alice, to: bob } }
+  connect:
+    resolve:
+      api.internal: [{ kind: servfail, latency_ms: 5 }, { kind: timeout }]
+    handshake:
+      "10.0.0.1:443": [{ kind: cert_verification, latency_ms: 3 }]
+"#;
+        let scenario: Scenario = serde_yaml::from_str(yaml).unwrap();
+        let result = ScenarioRunner::run(&scenario).unwrap();
+        assert!(result.passed());
+        assert_eq!(result.faults_injected, 1);
+
+        let outcomes: Vec<_> = result.connect_trace.iter().map(|e| &e.kind).collect();
+        let endpoint: std::net::SocketAddr = "10.0.0.1:443".parse().unwrap();
+        assert_eq!(
+            outcomes,
+            [
+                &ConnectTraceKind::Resolve {
+                    name: "api.internal".into(),
+                    outcome: ResolveOutcome::ServFail,
+                },
+                &ConnectTraceKind::Resolve {
+                    name: "api.internal".into(),
+                    outcome: ResolveOutcome::Timeout,
+                },
+                &ConnectTraceKind::Handshake {
+                    endpoint,
+                    outcome: HandshakeOutcome::CertVerification,
+                },
+            ]
+        );
+        // Scripted latencies advance the simulator's clock in order.
+        assert_eq!(result.connect_trace[1].time, Time::from_millis(5));
+        let json = result.to_json();
+        assert_eq!(json["connect_trace"][0]["outcome"], "servfail");
+        assert_eq!(json["connect_trace"][2]["handshake"], "10.0.0.1:443");
+
+        let replay = ScenarioRunner::run(&scenario).unwrap();
+        assert_eq!(replay.connect_trace, result.connect_trace);
+        crate::test_complete!("run_replays_connect_faults_end_to_end");
+    }
+
     #[test]
     fn run_with_all_fault_types() {
         init_test("run_with_all_fault_types");
@@ -1565,6 +1734,98 @@ mod tests {
         crate::test_complete!("explore_seeds_basic");
     }
 
+    fn scenario_with_partition_at(at_ms: u64) -> Scenario {
+        let mut scenario = minimal_scenario();
+        let args = {
+            let mut m = BTreeMap::new();
+            m.insert("from".into(), serde_json::json!("alice"));
+            m.insert("to".into(), serde_json::json!("bob"));
+            m
+        };
+        scenario.faults = vec![
+            FaultEvent {
+                at_ms,
+                action: FaultAction::Partition,
+                args: args.clone(),
+            },
+            FaultEvent {
+                at_ms,
+                action: FaultAction::Heal,
+                args,
+            },
+        ];
+        scenario
+    }
+
+    #[test]
+    fn limits_fail_run_with_first_exceeded_step() {
+        init_test("limits_fail_run_with_first_exceeded_step");
+        let mut scenario = scenario_with_partition_at(100);
+        scenario.limits = LimitsSection {
+            max_virtual_time_ms: Some(50),
+            max_live_tasks: Some(0),
+            ..LimitsSection::default()
+        };
+        let result = ScenarioRunner::run(&scenario).unwrap();
+        assert!(!result.passed());
+        let violations = &result.lab_report.resource_limit_violations;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].resource, LabResource::VirtualTimeNanos);
+        assert_eq!(violations[0].limit, 50_000_000);
+        assert_eq!(violations[0].peak, 100_000_000);
+        assert_eq!(violations[0].first_exceeded_step, 0);
+        assert!(
+            result
+                .lab_report
+                .invariant_violations
+                .iter()
+                .any(|v| v.starts_with("resource_limit:virtual_time_nanos:"))
+        );
+        let json = result.to_json();
+        assert_eq!(
+            json["resource_limit_violations"][0]["resource"],
+            "virtual_time_nanos"
+        );
+        crate::test_complete!("limits_fail_run_with_first_exceeded_step");
+    }
+
+    #[test]
+    fn passing_certificate_resources_stable_across_same_seed_runs() {
+        init_test("passing_certificate_resources_stable_across_same_seed_runs");
+        let mut scenario = scenario_with_partition_at(100);
+        scenario.limits = LimitsSection {
+            max_steps: Some(1_000),
+            max_virtual_time_ms: Some(100),
+            ..LimitsSection::default()
+        };
+        let first = ScenarioRunner::run(&scenario).unwrap();
+        let second = ScenarioRunner::run(&scenario).unwrap();
+        assert!(first.passed());
+        assert_eq!(first.certificate.resources, second.certificate.resources);
+        assert_eq!(first.certificate.resources.virtual_time_nanos, 100_000_000);
+        assert_eq!(
+            first.to_json()["certificate"]["resources"]["virtual_time_nanos"],
+            100_000_000
+        );
+        crate::test_complete!("passing_certificate_resources_stable_across_same_seed_runs");
+    }
+
+    #[test]
+    fn explore_seeds_aggregates_worst_case_resources() {
+        init_test("explore_seeds_aggregates_worst_case_resources");
+        let scenario = scenario_with_partition_at(25);
+        let result = ScenarioRunner::explore_seeds(&scenario, 7, 4).unwrap();
+        let expected = result
+            .runs
+            .iter()
+            .fold(LabResourceUsage::default(), |acc, run| {
+                acc.worst_case(&run.resources)
+            });
+        assert_eq!(result.worst_case_resources, expected);
+        assert_eq!(result.worst_case_resources.virtual_time_nanos, 25_000_000);
+        crate::test_complete!("explore_seeds_aggregates_worst_case_resources");
+    }
+
     #[test]
     fn explore_seeds_reports_each_run() {
         init_test("explore_seeds_reports_each_run");
@@ -1696,12 +1957,14 @@ mod tests {
                 schedule_hash: 2,
                 steps: 100,
                 trace_fingerprint: 3,
+                resources: LabResourceUsage::default(),
             },
             second: TraceCertificateSnapshot {
                 event_hash: 4,
                 schedule_hash: 5,
                 steps: 100,
                 trace_fingerprint: 6,
+                resources: LabResourceUsage::default(),
             },
         };
         let msg = err.to_string();
@@ -1720,6 +1983,7 @@ mod tests {
             schedule_hash: 222,
             steps: 333,
             trace_fingerprint: 444,
+            resources: LabResourceUsage::default(),
         };
         let cert2 = cert; // Copy
         let cert3 = cert;
@@ -1738,6 +2002,7 @@ mod tests {
             steps: 100,
             fingerprint: 999,
             failures: vec![],
+            resources: LabResourceUsage::default(),
         };
         let s2 = s;
         assert_eq!(s2.seed, 42);
@@ -1763,8 +2028,10 @@ mod tests {
                 steps: 50,
                 fingerprint: 1,
                 failures: vec![],
+                resources: LabResourceUsage::default(),
             }],
             first_failure_seed: Some(5),
+            worst_case_resources: LabResourceUsage::default(),
         };
         let r2 = r;
         assert_eq!(r2.scenario_id, "test-explore");
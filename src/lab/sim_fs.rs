@@ -0,0 +1,1926 @@
+//! Deterministic simulated filesystem for lab testing.
+//!
+//! [`SimFs`] implements [`Vfs`], so code written against the
+//! [`fs::vfs`](crate::fs::vfs) traits runs unmodified on the real filesystem
+//! ([`UnixVfs`](crate::fs::UnixVfs)) or on this simulation. The simulation
+//! adds what the real filesystem cannot give a test:
+//!
+//! - **Virtual-time latency** per operation class ([`SimFsOp`]), configured
+//!   per path glob with the same [`LatencyModel`]s as the virtual network.
+//!   Every operation advances the simulated disk clock ([`SimFs::now`]).
+//! - **Injected failures** ([`SimFsFailure`]): `ENOSPC`, `EIO`, torn writes
+//!   that persist only a prefix of the buffer, and fsyncs that report success
+//!   without persisting anything. Failures fire either probabilistically from
+//!   the seeded RNG ([`SimFsRule::fail`]) or from fault events
+//!   ([`SimFsFault`]) injected directly, scheduled at a virtual time, or
+//!   translated from scenario [`FaultEvent`]s.
+//! - **Durability**: file data is persistent only after a successful
+//!   `sync_all`/`sync_data`. A [`SimFsFault::Crash`] rolls every file back to
+//!   its last synced contents and invalidates all open handles. Namespace
+//!   operations (create, rename, remove, mkdir, link) are journaled: they are
+//!   durable when they return, so the write-temp / fsync / rename pattern
+//!   behaves as on a journaling filesystem.
+//! - **Trace**: every decision (latency sampled, failure injected, fsync lie,
+//!   crash loss) is appended to [`SimFs::trace`]. The same seed, rules, and
+//!   operation sequence reproduce the same trace.
+//!
+//! Symlinks are not simulated: [`Vfs::read_link`] fails with `EINVAL`, and
+//! metadata never reports a symlink. An unlinked file's contents are dropped
+//! immediately; handles still open on it fail with `ESTALE`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::fs::Vfs;
+//! use asupersync::lab::sim_fs::{SimFs, SimFsConfig, SimFsFailure, SimFsFault, SimFsOp, SimFsRule};
+//! use asupersync::lab::network::LatencyModel;
+//!
+//! let fs = SimFs::new(
+//!     SimFsConfig::new(42)
+//!         .rule(SimFsRule::new("/data/**").latency(SimFsOp::Fsync, LatencyModel::Fixed(ms(5))))
+//!         .rule(SimFsRule::new("/data/*.log").fail(SimFsFailure::FsyncLie, 0.01)),
+//! );
+//! fs.inject(SimFsFault::Arm { glob: "/data/out".into(), failure: SimFsFailure::TornWrite, count: 1 });
+//! fs.write(Path::new("/data/out"), b"payload").await?; // torn
+//! fs.inject(SimFsFault::Crash);
+//! ```
+
+use super::network::LatencyModel;
+use super::scenario::{FaultAction, FaultEvent};
+use crate::fs::{
+    DirEntry, Metadata, OpenOptions, Permissions, ReadDir, Vfs, VfsFile, VirtualFileKind,
+    VirtualMetadata,
+};
+use crate::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+use crate::time::TimeSource;
+use crate::types::Time;
+use crate::util::DetRng;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::io::{self, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+/// Default mode for files created without an explicit mode (0o666 & ~0o022).
+const DEFAULT_FILE_MODE: u32 = 0o644;
+/// Default mode for directories.
+const DEFAULT_DIR_MODE: u32 = 0o755;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Operation class used to select latency models and failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimFsOp {
+    /// Opening or creating a file.
+    Open,
+    /// Reading file data (handle reads and whole-file reads).
+    Read,
+    /// Writing file data (handle writes, whole-file writes, copy, `set_len`).
+    Write,
+    /// `sync_all` / `sync_data`.
+    Fsync,
+    /// Metadata and namespace operations (stat, mkdir, rename, remove, list).
+    Metadata,
+}
+
+/// A failure the simulation can inject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimFsFailure {
+    /// The operation fails with `EIO` without side effects.
+    Io(SimFsOp),
+    /// A write fails with `ENOSPC` without side effects.
+    NoSpace,
+    /// A write persists a seeded-random strict prefix of its buffer, then
+    /// fails with `EIO`.
+    TornWrite,
+    /// An fsync reports success but persists nothing.
+    FsyncLie,
+}
+
+impl SimFsFailure {
+    /// The operation class this failure applies to.
+    #[must_use]
+    pub const fn op(self) -> SimFsOp {
+        match self {
+            Self::Io(op) => op,
+            Self::NoSpace | Self::TornWrite => SimFsOp::Write,
+            Self::FsyncLie => SimFsOp::Fsync,
+        }
+    }
+}
+
+/// Latency and probabilistic failures for paths matching a glob.
+///
+/// Globs match whole path components: `*` and `?` match within one
+/// component, and a `**` component matches any number of components.
+#[derive(Debug, Clone)]
+pub struct SimFsRule {
+    glob: String,
+    latency: Vec<(SimFsOp, LatencyModel)>,
+    failures: Vec<(SimFsFailure, f64)>,
+}
+
+impl SimFsRule {
+    /// Creates a rule for paths matching `glob`.
+    #[must_use]
+    pub fn new(glob: impl Into<String>) -> Self {
+        Self {
+            glob: glob.into(),
+            latency: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    /// Sets the latency model for `op` on matching paths.
+    #[must_use]
+    pub fn latency(mut self, op: SimFsOp, model: LatencyModel) -> Self {
+        self.latency.retain(|(existing, _)| *existing != op);
+        self.latency.push((op, model));
+        self
+    }
+
+    /// Injects `failure` with the given probability (0.0 - 1.0) on each
+    /// matching operation of class [`SimFsFailure::op`].
+    #[must_use]
+    pub fn fail(mut self, failure: SimFsFailure, probability: f64) -> Self {
+        self.failures.push((failure, probability));
+        self
+    }
+}
+
+/// Configuration for a [`SimFs`].
+#[derive(Debug, Clone)]
+pub struct SimFsConfig {
+    /// Seed for latency sampling, probabilistic failures, and torn-write
+    /// prefix lengths.
+    pub seed: u64,
+    /// Total bytes of file data the simulated disk holds; writes beyond it
+    /// fail with `ENOSPC`. `None` is unbounded.
+    pub capacity_bytes: Option<u64>,
+    /// Rules in priority order: the first rule that sets a latency for an
+    /// operation class wins; probabilistic failures of every matching rule
+    /// are evaluated in order.
+    pub rules: Vec<SimFsRule>,
+}
+
+impl Default for SimFsConfig {
+    fn default() -> Self {
+        Self::new(0x5349_4D46)
+    }
+}
+
+impl SimFsConfig {
+    /// Creates an unbounded, zero-latency, fault-free configuration.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            capacity_bytes: None,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Sets the disk capacity in bytes.
+    #[must_use]
+    pub fn capacity_bytes(mut self, bytes: u64) -> Self {
+        self.capacity_bytes = Some(bytes);
+        self
+    }
+
+    /// Appends a rule.
+    #[must_use]
+    pub fn rule(mut self, rule: SimFsRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+/// A fault event applied to the simulated filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimFsFault {
+    /// Fail the next `count` operations of class [`SimFsFailure::op`] on
+    /// paths matching `glob`.
+    Arm {
+        /// Path glob.
+        glob: String,
+        /// Failure to inject.
+        failure: SimFsFailure,
+        /// Number of operations to fail.
+        count: u32,
+    },
+    /// Every write to paths matching `glob` fails with `ENOSPC` until a
+    /// matching [`SimFsFault::DiskRecovered`].
+    DiskFull {
+        /// Path glob.
+        glob: String,
+    },
+    /// Lifts a [`SimFsFault::DiskFull`] with the same glob.
+    DiskRecovered {
+        /// Path glob.
+        glob: String,
+    },
+    /// Simulated crash: discard every write not covered by a successful
+    /// fsync and invalidate all open handles.
+    Crash,
+}
+
+// ---------------------------------------------------------------------------
+// Trace
+// ---------------------------------------------------------------------------
+
+/// What the simulation decided for one operation or fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimFsDecision {
+    /// No failure was injected; the operation ran its normal semantics
+    /// (which may still fail, e.g. with `ENOENT`).
+    Proceeded,
+    /// An `EIO` or `ENOSPC` failure was injected.
+    Failed(SimFsFailure),
+    /// A torn write persisted `persisted` of `requested` bytes.
+    Torn {
+        /// Bytes the caller asked to write.
+        requested: usize,
+        /// Bytes that reached the file.
+        persisted: usize,
+    },
+    /// An fsync reported success without persisting.
+    FsyncLied,
+    /// A fault event was applied.
+    FaultApplied(SimFsFault),
+    /// A crash rolled files back to their synced contents.
+    Crashed {
+        /// Files whose contents changed.
+        lost_files: usize,
+        /// Bytes of unsynced data discarded (length differences plus
+        /// overwritten bytes).
+        lost_bytes: u64,
+    },
+}
+
+/// One entry of the [`SimFs`] decision trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimFsTraceEvent {
+    /// Sequence number, starting at 0.
+    pub seq: u64,
+    /// Simulated disk time when the operation completed.
+    pub time: Time,
+    /// Operation class, or `None` for fault events.
+    pub op: Option<SimFsOp>,
+    /// Path the operation targeted, or `None` for fault events.
+    pub path: Option<PathBuf>,
+    /// Sampled latency.
+    pub latency: Duration,
+    /// Decision.
+    pub decision: SimFsDecision,
+}
+
+// ---------------------------------------------------------------------------
+// State
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Inode {
+    kind: VirtualFileKind,
+    /// Contents visible to reads.
+    data: Vec<u8>,
+    /// Contents that survive a crash.
+    durable: Vec<u8>,
+    mode: u32,
+    created: Time,
+    modified: Time,
+}
+
+impl Inode {
+    fn new(kind: VirtualFileKind, mode: u32, now: Time) -> Self {
+        Self {
+            kind,
+            data: Vec::new(),
+            durable: Vec::new(),
+            mode,
+            created: now,
+            modified: now,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ArmedFault {
+    glob: String,
+    failure: SimFsFailure,
+    remaining: u32,
+}
+
+#[derive(Debug)]
+struct ScheduledFault {
+    at: Time,
+    fault: SimFsFault,
+}
+
+#[derive(Debug)]
+struct SimFsState {
+    config: SimFsConfig,
+    rng: DetRng,
+    now: Time,
+    /// Bumped on crash; handles from an older epoch are dead.
+    epoch: u64,
+    entries: BTreeMap<PathBuf, u64>,
+    inodes: BTreeMap<u64, Inode>,
+    next_inode: u64,
+    armed: Vec<ArmedFault>,
+    full: Vec<String>,
+    /// Sorted by time, then insertion order.
+    scheduled: Vec<ScheduledFault>,
+    trace: Vec<SimFsTraceEvent>,
+}
+
+impl SimFsState {
+    fn new(config: SimFsConfig) -> Self {
+        let rng = DetRng::new(config.seed);
+        let mut state = Self {
+            config,
+            rng,
+            now: Time::ZERO,
+            epoch: 0,
+            entries: BTreeMap::new(),
+            inodes: BTreeMap::new(),
+            next_inode: 1,
+            armed: Vec::new(),
+            full: Vec::new(),
+            scheduled: Vec::new(),
+            trace: Vec::new(),
+        };
+        let root = state.alloc_inode(VirtualFileKind::Dir, DEFAULT_DIR_MODE);
+        state.entries.insert(PathBuf::from("/"), root);
+        state
+    }
+
+    // -- simulation core --
+
+    /// Fires due scheduled faults, samples latency for `op` on `path`,
+    /// advances the clock, and decides which failure (if any) to inject.
+    fn begin(&mut self, op: SimFsOp, path: &Path) -> (Duration, Option<SimFsFailure>) {
+        self.fire_due_faults();
+        let latency = self.sample_latency(op, path);
+        self.now = self.now + latency;
+        let failure = self.decide(op, path);
+        (latency, failure)
+    }
+
+    fn sample_latency(&mut self, op: SimFsOp, path: &Path) -> Duration {
+        let model = self
+            .config
+            .rules
+            .iter()
+            .filter(|rule| glob_match(&rule.glob, path))
+            .find_map(|rule| {
+                rule.latency
+                    .iter()
+                    .find(|(class, _)| *class == op)
+                    .map(|(_, model)| model.clone())
+            });
+        model.map_or(Duration::ZERO, |model| model.sample(&mut self.rng))
+    }
+
+    fn decide(&mut self, op: SimFsOp, path: &Path) -> Option<SimFsFailure> {
+        if let Some(index) = self
+            .armed
+            .iter()
+            .position(|armed| armed.failure.op() == op && glob_match(&armed.glob, path))
+        {
+            let armed = &mut self.armed[index];
+            let failure = armed.failure;
+            armed.remaining -= 1;
+            if armed.remaining == 0 {
+                self.armed.remove(index);
+            }
+            return Some(failure);
+        }
+        if op == SimFsOp::Write && self.full.iter().any(|glob| glob_match(glob, path)) {
+            return Some(SimFsFailure::NoSpace);
+        }
+        let candidates: Vec<(SimFsFailure, f64)> = self
+            .config
+            .rules
+            .iter()
+            .filter(|rule| glob_match(&rule.glob, path))
+            .flat_map(|rule| rule.failures.iter().copied())
+            .filter(|(failure, _)| failure.op() == op)
+            .collect();
+        candidates
+            .into_iter()
+            .find(|(_, probability)| self.chance(*probability))
+            .map(|(failure, _)| failure)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn chance(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        if probability >= 1.0 {
+            return true;
+        }
+        let sample = (self.rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+
+    fn record(
+        &mut self,
+        op: Option<SimFsOp>,
+        path: Option<&Path>,
+        latency: Duration,
+        decision: SimFsDecision,
+    ) {
+        let seq = self.trace.len() as u64;
+        self.trace.push(SimFsTraceEvent {
+            seq,
+            time: self.now,
+            op,
+            path: path.map(Path::to_path_buf),
+            latency,
+            decision,
+        });
+    }
+
+    /// Records the outcome of `begin` and converts an injected `EIO` /
+    /// `ENOSPC` into the error to return. Torn writes and fsync lies are
+    /// handled by their callers.
+    fn settle(
+        &mut self,
+        op: SimFsOp,
+        path: &Path,
+        latency: Duration,
+        failure: Option<SimFsFailure>,
+    ) -> io::Result<()> {
+        match failure {
+            Some(failure @ (SimFsFailure::Io(_) | SimFsFailure::NoSpace)) => {
+                self.record(
+                    Some(op),
+                    Some(path),
+                    latency,
+                    SimFsDecision::Failed(failure),
+                );
+                Err(failure_error(failure))
+            }
+            _ => {
+                self.record(Some(op), Some(path), latency, SimFsDecision::Proceeded);
+                Ok(())
+            }
+        }
+    }
+
+    fn fire_due_faults(&mut self) {
+        while self
+            .scheduled
+            .first()
+            .is_some_and(|scheduled| scheduled.at <= self.now)
+        {
+            let scheduled = self.scheduled.remove(0);
+            self.apply(scheduled.fault);
+        }
+    }
+
+    fn apply(&mut self, fault: SimFsFault) {
+        match &fault {
+            SimFsFault::Arm {
+                glob,
+                failure,
+                count,
+            } => {
+                if *count > 0 {
+                    self.armed.push(ArmedFault {
+                        glob: glob.clone(),
+                        failure: *failure,
+                        remaining: *count,
+                    });
+                }
+            }
+            SimFsFault::DiskFull { glob } => {
+                if !self.full.contains(glob) {
+                    self.full.push(glob.clone());
+                }
+            }
+            SimFsFault::DiskRecovered { glob } => self.full.retain(|full| full != glob),
+            SimFsFault::Crash => {}
+        }
+        self.record(
+            None,
+            None,
+            Duration::ZERO,
+            SimFsDecision::FaultApplied(fault.clone()),
+        );
+        if fault == SimFsFault::Crash {
+            self.crash();
+        }
+    }
+
+    fn crash(&mut self) {
+        let mut lost_files = 0;
+        let mut lost_bytes = 0u64;
+        for inode in self.inodes.values_mut() {
+            if inode.kind != VirtualFileKind::File || inode.data == inode.durable {
+                continue;
+            }
+            lost_files += 1;
+            let common = inode.data.len().min(inode.durable.len());
+            let overwritten = inode.data[..common]
+                .iter()
+                .zip(&inode.durable[..common])
+                .filter(|(a, b)| a != b)
+                .count();
+            let length_delta = inode.data.len().abs_diff(inode.durable.len());
+            lost_bytes += (overwritten + length_delta) as u64;
+            inode.data.clone_from(&inode.durable);
+        }
+        self.epoch += 1;
+        self.record(
+            None,
+            None,
+            Duration::ZERO,
+            SimFsDecision::Crashed {
+                lost_files,
+                lost_bytes,
+            },
+        );
+    }
+
+    // -- namespace --
+
+    fn alloc_inode(&mut self, kind: VirtualFileKind, mode: u32) -> u64 {
+        let id = self.next_inode;
+        self.next_inode += 1;
+        self.inodes.insert(id, Inode::new(kind, mode, self.now));
+        id
+    }
+
+    fn lookup(&self, path: &Path) -> io::Result<u64> {
+        self.entries
+            .get(path)
+            .copied()
+            .ok_or_else(|| errno(libc::ENOENT))
+    }
+
+    fn inode(&self, id: u64) -> io::Result<&Inode> {
+        self.inodes.get(&id).ok_or_else(|| errno(libc::ESTALE))
+    }
+
+    fn inode_mut(&mut self, id: u64) -> io::Result<&mut Inode> {
+        self.inodes.get_mut(&id).ok_or_else(|| errno(libc::ESTALE))
+    }
+
+    fn kind_of(&self, path: &Path) -> Option<VirtualFileKind> {
+        let id = self.entries.get(path)?;
+        self.inodes.get(id).map(|inode| inode.kind)
+    }
+
+    /// Checks that the parent of `path` exists and is a directory.
+    fn require_parent_dir(&self, path: &Path) -> io::Result<()> {
+        let Some(parent) = path.parent() else {
+            return Err(errno(libc::EEXIST));
+        };
+        match self.kind_of(parent) {
+            Some(VirtualFileKind::Dir) => Ok(()),
+            Some(VirtualFileKind::File) => Err(errno(libc::ENOTDIR)),
+            None => Err(errno(libc::ENOENT)),
+        }
+    }
+
+    fn children(&self, dir: &Path) -> Vec<PathBuf> {
+        self.entries
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .cloned()
+            .collect()
+    }
+
+    fn descendants(&self, dir: &Path) -> Vec<PathBuf> {
+        self.entries
+            .keys()
+            .filter(|path| path.as_path() != dir && path.starts_with(dir))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes a namespace entry, dropping its inode once no entry
+    /// references it.
+    fn unlink(&mut self, path: &Path) {
+        if let Some(id) = self.entries.remove(path)
+            && !self.entries.values().any(|other| *other == id)
+        {
+            self.inodes.remove(&id);
+        }
+    }
+
+    fn used_bytes(&self) -> u64 {
+        self.inodes
+            .values()
+            .map(|inode| inode.data.len() as u64)
+            .sum()
+    }
+
+    /// Fails with `ENOSPC` if growing a file by `growth` bytes would exceed
+    /// the configured capacity.
+    fn check_capacity(&self, growth: u64) -> io::Result<()> {
+        match self.config.capacity_bytes {
+            Some(capacity) if self.used_bytes().saturating_add(growth) > capacity => {
+                Err(failure_error(SimFsFailure::NoSpace))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn metadata_of(&self, id: u64) -> io::Result<Metadata> {
+        let inode = self.inode(id)?;
+        Ok(Metadata::from_virtual(VirtualMetadata {
+            kind: inode.kind,
+            len: inode.data.len() as u64,
+            permissions: Permissions::from_mode(inode.mode),
+            modified: system_time(inode.modified),
+            created: system_time(inode.created),
+        }))
+    }
+
+    /// Writes `buf` at `offset` into inode `id`, applying injected failures.
+    /// Returns the number of bytes written.
+    fn write_at(
+        &mut self,
+        id: u64,
+        path: &Path,
+        offset: u64,
+        buf: &[u8],
+        latency: Duration,
+        failure: Option<SimFsFailure>,
+    ) -> io::Result<usize> {
+        let len = self.inode(id)?.data.len() as u64;
+        let end = offset.saturating_add(buf.len() as u64);
+        let growth = end.saturating_sub(len);
+        let failure = match failure {
+            None | Some(SimFsFailure::TornWrite) if self.check_capacity(growth).is_err() => {
+                Some(SimFsFailure::NoSpace)
+            }
+            other => other,
+        };
+        if failure == Some(SimFsFailure::TornWrite) {
+            let persisted = if buf.len() > 1 {
+                self.rng.next_usize(buf.len())
+            } else {
+                0
+            };
+            self.splice(id, offset, &buf[..persisted])?;
+            self.record(
+                Some(SimFsOp::Write),
+                Some(path),
+                latency,
+                SimFsDecision::Torn {
+                    requested: buf.len(),
+                    persisted,
+                },
+            );
+            return Err(errno(libc::EIO));
+        }
+        self.settle(SimFsOp::Write, path, latency, failure)?;
+        self.splice(id, offset, buf)?;
+        Ok(buf.len())
+    }
+
+    fn splice(&mut self, id: u64, offset: u64, bytes: &[u8]) -> io::Result<()> {
+        let now = self.now;
+        let inode = self.inode_mut(id)?;
+        let offset = usize::try_from(offset).map_err(|_| errno(libc::EFBIG))?;
+        let end = offset + bytes.len();
+        if inode.data.len() < end {
+            inode.data.resize(end, 0);
+        }
+        inode.data[offset..end].copy_from_slice(bytes);
+        inode.modified = now;
+        Ok(())
+    }
+
+    fn open(&mut self, path: &Path, opts: &OpenOptions) -> io::Result<OpenedFile> {
+        let flags = opts.flags();
+        let writable = flags.write || flags.append;
+        if !(flags.read || writable)
+            || ((flags.create || flags.create_new || flags.truncate) && !writable)
+            || (flags.truncate && flags.append)
+        {
+            return Err(errno(libc::EINVAL));
+        }
+        let (latency, failure) = self.begin(SimFsOp::Open, path);
+        self.settle(SimFsOp::Open, path, latency, failure)?;
+        let id = match self.entries.get(path).copied() {
+            Some(_) if flags.create_new => return Err(errno(libc::EEXIST)),
+            Some(id) => {
+                let inode = self.inode_mut(id)?;
+                if inode.kind == VirtualFileKind::Dir && writable {
+                    return Err(errno(libc::EISDIR));
+                }
+                if flags.truncate {
+                    inode.data.clear();
+                }
+                id
+            }
+            None if flags.create || flags.create_new => {
+                self.require_parent_dir(path)?;
+                let id = self.alloc_inode(
+                    VirtualFileKind::File,
+                    flags.mode.unwrap_or(DEFAULT_FILE_MODE),
+                );
+                self.entries.insert(path.to_path_buf(), id);
+                id
+            }
+            None => return Err(errno(libc::ENOENT)),
+        };
+        Ok(OpenedFile {
+            inode: id,
+            epoch: self.epoch,
+            readable: flags.read,
+            writable,
+            append: flags.append,
+        })
+    }
+}
+
+struct OpenedFile {
+    inode: u64,
+    epoch: u64,
+    readable: bool,
+    writable: bool,
+    append: bool,
+}
+
+fn errno(code: i32) -> io::Error {
+    io::Error::from_raw_os_error(code)
+}
+
+fn failure_error(failure: SimFsFailure) -> io::Error {
+    match failure {
+        SimFsFailure::NoSpace => errno(libc::ENOSPC),
+        _ => errno(libc::EIO),
+    }
+}
+
+fn system_time(time: Time) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_nanos(time.as_nanos())
+}
+
+/// Resolves `path` lexically against `/`.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::from("/");
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+    out
+}
+
+fn glob_match(pattern: &str, path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    glob_match_parts(&pattern, &segments)
+}
+
+fn glob_match_parts(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_match_parts(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, tail)| {
+            segment_match(first.as_bytes(), segment.as_bytes()) && glob_match_parts(rest, tail)
+        }),
+    }
+}
+
+fn segment_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| segment_match(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && segment_match(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && segment_match(rest, &text[1..]),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SimFs
+// ---------------------------------------------------------------------------
+
+/// Deterministic simulated filesystem. Cloning shares the same disk.
+#[derive(Debug, Clone)]
+pub struct SimFs {
+    state: Arc<Mutex<SimFsState>>,
+}
+
+impl Default for SimFs {
+    fn default() -> Self {
+        Self::new(SimFsConfig::default())
+    }
+}
+
+impl SimFs {
+    /// Creates an empty filesystem containing only `/`.
+    #[must_use]
+    pub fn new(config: SimFsConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SimFsState::new(config))),
+        }
+    }
+
+    /// Returns the simulated disk time.
+    #[must_use]
+    pub fn now(&self) -> Time {
+        self.state.lock().now
+    }
+
+    /// Advances the disk clock to `time` (never backwards), firing scheduled
+    /// faults that come due. Use it to keep the disk clock in step with a
+    /// lab runtime's virtual clock.
+    pub fn advance_to(&self, time: Time) {
+        let mut state = self.state.lock();
+        if time > state.now {
+            state.now = time;
+        }
+        state.fire_due_faults();
+    }
+
+    /// Applies a fault immediately.
+    pub fn inject(&self, fault: SimFsFault) {
+        self.state.lock().apply(fault);
+    }
+
+    /// Schedules a fault for when the disk clock reaches `at`. Due faults
+    /// fire before the next operation starts.
+    pub fn schedule(&self, at: Time, fault: SimFsFault) {
+        let mut state = self.state.lock();
+        let index = state
+            .scheduled
+            .partition_point(|scheduled| scheduled.at <= at);
+        state.scheduled.insert(index, ScheduledFault { at, fault });
+    }
+
+    /// Applies the filesystem-relevant subset of a scenario fault:
+    ///
+    /// - `disk_pressure { path }` → [`SimFsFault::DiskFull`] with `path` as
+    ///   the glob,
+    /// - `disk_recovered { path }` → [`SimFsFault::DiskRecovered`],
+    /// - `host_crash` → [`SimFsFault::Crash`].
+    ///
+    /// The event's `at_ms` schedules the fault on the disk clock. Returns
+    /// `false` (and does nothing) for other actions or missing arguments.
+    pub fn apply_scenario_fault(&self, event: &FaultEvent) -> bool {
+        let path = || {
+            event
+                .args
+                .get("path")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        };
+        let fault = match event.action {
+            FaultAction::DiskPressure => path().map(|glob| SimFsFault::DiskFull { glob }),
+            FaultAction::DiskRecovered => path().map(|glob| SimFsFault::DiskRecovered { glob }),
+            FaultAction::HostCrash => Some(SimFsFault::Crash),
+            _ => None,
+        };
+        let Some(fault) = fault else {
+            return false;
+        };
+        self.schedule(Time::from_millis(event.at_ms), fault);
+        true
+    }
+
+    /// Returns a copy of the decision trace.
+    #[must_use]
+    pub fn trace(&self) -> Vec<SimFsTraceEvent> {
+        self.state.lock().trace.clone()
+    }
+
+    /// Returns the contents that would survive a crash right now, or `None`
+    /// if `path` is not a file.
+    #[must_use]
+    pub fn durable_contents(&self, path: &Path) -> Option<Vec<u8>> {
+        let state = self.state.lock();
+        let id = state.entries.get(&normalize(path))?;
+        state
+            .inodes
+            .get(id)
+            .filter(|inode| inode.kind == VirtualFileKind::File)
+            .map(|inode| inode.durable.clone())
+    }
+
+    /// Returns the bytes of file data currently stored.
+    #[must_use]
+    pub fn used_bytes(&self) -> u64 {
+        self.state.lock().used_bytes()
+    }
+
+    /// Runs a namespace operation under the [`SimFsOp::Metadata`] class.
+    fn namespace<T>(
+        &self,
+        path: &Path,
+        op: impl FnOnce(&mut SimFsState, &Path) -> io::Result<T>,
+    ) -> io::Result<T> {
+        let path = normalize(path);
+        let mut state = self.state.lock();
+        let (latency, failure) = state.begin(SimFsOp::Metadata, &path);
+        state.settle(SimFsOp::Metadata, &path, latency, failure)?;
+        op(&mut state, &path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SimFsFile
+// ---------------------------------------------------------------------------
+
+/// An open file on a [`SimFs`].
+#[derive(Debug)]
+pub struct SimFsFile {
+    state: Arc<Mutex<SimFsState>>,
+    path: PathBuf,
+    inode: u64,
+    epoch: u64,
+    pos: u64,
+    readable: bool,
+    writable: bool,
+    append: bool,
+}
+
+impl SimFsFile {
+    fn check_live(&self, state: &SimFsState) -> io::Result<()> {
+        // A crash killed the process that opened this handle.
+        if state.epoch == self.epoch {
+            Ok(())
+        } else {
+            Err(errno(libc::EIO))
+        }
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        let mut state = self.state.lock();
+        self.check_live(&state)?;
+        let (latency, failure) = state.begin(SimFsOp::Fsync, &self.path);
+        if failure == Some(SimFsFailure::FsyncLie) {
+            state.inode(self.inode)?;
+            state.record(
+                Some(SimFsOp::Fsync),
+                Some(&self.path),
+                latency,
+                SimFsDecision::FsyncLied,
+            );
+            return Ok(());
+        }
+        state.settle(SimFsOp::Fsync, &self.path, latency, failure)?;
+        let inode = state.inode_mut(self.inode)?;
+        inode.durable.clone_from(&inode.data);
+        Ok(())
+    }
+}
+
+impl AsyncRead for SimFsFile {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        let mut state = this.state.lock();
+        let result = (|| {
+            this.check_live(&state)?;
+            if !this.readable {
+                return Err(errno(libc::EBADF));
+            }
+            let (latency, failure) = state.begin(SimFsOp::Read, &this.path);
+            state.settle(SimFsOp::Read, &this.path, latency, failure)?;
+            let data = &state.inode(this.inode)?.data;
+            let start = usize::try_from(this.pos)
+                .unwrap_or(usize::MAX)
+                .min(data.len());
+            let n = buf.remaining().min(data.len() - start);
+            buf.put_slice(&data[start..start + n]);
+            Ok(n)
+        })();
+        drop(state);
+        Poll::Ready(result.map(|n| this.pos += n as u64))
+    }
+}
+
+impl AsyncWrite for SimFsFile {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let mut state = this.state.lock();
+        let result = (|| {
+            this.check_live(&state)?;
+            if !this.writable {
+                return Err(errno(libc::EBADF));
+            }
+            let (latency, failure) = state.begin(SimFsOp::Write, &this.path);
+            let offset = if this.append {
+                state.inode(this.inode)?.data.len() as u64
+            } else {
+                this.pos
+            };
+            let written = state.write_at(this.inode, &this.path, offset, buf, latency, failure)?;
+            Ok(offset + written as u64)
+        })();
+        drop(state);
+        Poll::Ready(result.map(|end| {
+            let written = buf.len();
+            this.pos = end;
+            written
+        }))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for SimFsFile {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = &mut *self;
+        let state = this.state.lock();
+        let result = (|| {
+            this.check_live(&state)?;
+            let len = state.inode(this.inode)?.data.len() as u64;
+            let target = match pos {
+                SeekFrom::Start(offset) => Some(offset),
+                SeekFrom::End(delta) => len.checked_add_signed(delta),
+                SeekFrom::Current(delta) => this.pos.checked_add_signed(delta),
+            };
+            target.ok_or_else(|| errno(libc::EINVAL))
+        })();
+        drop(state);
+        Poll::Ready(result.map(|target| {
+            this.pos = target;
+            target
+        }))
+    }
+}
+
+impl VfsFile for SimFsFile {
+    async fn metadata(&self) -> io::Result<Metadata> {
+        let mut state = self.state.lock();
+        self.check_live(&state)?;
+        let (latency, failure) = state.begin(SimFsOp::Metadata, &self.path);
+        state.settle(SimFsOp::Metadata, &self.path, latency, failure)?;
+        state.metadata_of(self.inode)
+    }
+
+    async fn sync_all(&self) -> io::Result<()> {
+        self.sync()
+    }
+
+    async fn sync_data(&self) -> io::Result<()> {
+        self.sync()
+    }
+
+    async fn set_len(&self, size: u64) -> io::Result<()> {
+        let mut state = self.state.lock();
+        self.check_live(&state)?;
+        if !self.writable {
+            return Err(errno(libc::EINVAL));
+        }
+        let (latency, failure) = state.begin(SimFsOp::Write, &self.path);
+        let len = state.inode(self.inode)?.data.len() as u64;
+        let failure = match failure {
+            Some(SimFsFailure::TornWrite) => Some(SimFsFailure::Io(SimFsOp::Write)),
+            None if state.check_capacity(size.saturating_sub(len)).is_err() => {
+                Some(SimFsFailure::NoSpace)
+            }
+            other => other,
+        };
+        state.settle(SimFsOp::Write, &self.path, latency, failure)?;
+        let size = usize::try_from(size).map_err(|_| errno(libc::EFBIG))?;
+        let now = state.now;
+        let inode = state.inode_mut(self.inode)?;
+        inode.data.resize(size, 0);
+        inode.modified = now;
+        Ok(())
+    }
+
+    async fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        let mut state = self.state.lock();
+        self.check_live(&state)?;
+        let (latency, failure) = state.begin(SimFsOp::Metadata, &self.path);
+        state.settle(SimFsOp::Metadata, &self.path, latency, failure)?;
+        state.inode_mut(self.inode)?.mode = perm.mode();
+        Ok(())
+    }
+}
+
+/// The simulated disk clock, so an
+/// [`IoScheduler`](crate::fs::io_scheduler::IoScheduler) measures wait
+/// times in disk time.
+impl TimeSource for SimFs {
+    fn now(&self) -> Time {
+        Self::now(self)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Vfs impl
+// ---------------------------------------------------------------------------
+
+impl Vfs for SimFs {
+    type File = SimFsFile;
+
+    async fn open(&self, path: &Path, opts: &OpenOptions) -> io::Result<Self::File> {
+        let path = normalize(path);
+        let opened = self.state.lock().open(&path, opts)?;
+        Ok(SimFsFile {
+            state: Arc::clone(&self.state),
+            path,
+            inode: opened.inode,
+            epoch: opened.epoch,
+            pos: 0,
+            readable: opened.readable,
+            writable: opened.writable,
+            append: opened.append,
+        })
+    }
+
+    async fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.namespace(path, |state, path| state.metadata_of(state.lookup(path)?))
+    }
+
+    async fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.metadata(path).await
+    }
+
+    async fn set_permissions(&self, path: &Path, perm: Permissions) -> io::Result<()> {
+        self.namespace(path, |state, path| {
+            let id = state.lookup(path)?;
+            state.inode_mut(id)?.mode = perm.mode();
+            Ok(())
+        })
+    }
+
+    async fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.namespace(path, |state, path| {
+            if state.entries.contains_key(path) {
+                return Err(errno(libc::EEXIST));
+            }
+            state.require_parent_dir(path)?;
+            let id = state.alloc_inode(VirtualFileKind::Dir, DEFAULT_DIR_MODE);
+            state.entries.insert(path.to_path_buf(), id);
+            Ok(())
+        })
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.namespace(path, |state, path| {
+            let mut ancestors: Vec<&Path> = path.ancestors().collect();
+            ancestors.reverse();
+            for dir in ancestors {
+                match state.kind_of(dir) {
+                    Some(VirtualFileKind::Dir) => {}
+                    Some(VirtualFileKind::File) => return Err(errno(libc::ENOTDIR)),
+                    None => {
+                        let id = state.alloc_inode(VirtualFileKind::Dir, DEFAULT_DIR_MODE);
+                        state.entries.insert(dir.to_path_buf(), id);
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        self.namespace(path, |state, path| {
+            match state.kind_of(path) {
+                Some(VirtualFileKind::Dir) => {}
+                Some(VirtualFileKind::File) => return Err(errno(libc::ENOTDIR)),
+                None => return Err(errno(libc::ENOENT)),
+            }
+            if path == Path::new("/") {
+                return Err(errno(libc::EBUSY));
+            }
+            if !state.children(path).is_empty() {
+                return Err(errno(libc::ENOTEMPTY));
+            }
+            state.unlink(path);
+            Ok(())
+        })
+    }
+
+    async fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.namespace(path, |state, path| {
+            match state.kind_of(path) {
+                Some(VirtualFileKind::Dir) => {}
+                Some(VirtualFileKind::File) => return Err(errno(libc::ENOTDIR)),
+                None => return Err(errno(libc::ENOENT)),
+            }
+            if path == Path::new("/") {
+                return Err(errno(libc::EBUSY));
+            }
+            for descendant in state.descendants(path) {
+                state.unlink(&descendant);
+            }
+            state.unlink(path);
+            Ok(())
+        })
+    }
+
+    async fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
+        self.namespace(path, |state, path| {
+            match state.kind_of(path) {
+                Some(VirtualFileKind::Dir) => {}
+                Some(VirtualFileKind::File) => return Err(errno(libc::ENOTDIR)),
+                None => return Err(errno(libc::ENOENT)),
+            }
+            let entries = state
+                .children(path)
+                .into_iter()
+                .map(|child| {
+                    let metadata = state.metadata_of(state.lookup(&child)?)?;
+                    Ok(DirEntry::from_virtual(child, metadata))
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            Ok(ReadDir::from_virtual(entries))
+        })
+    }
+
+    async fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.namespace(path, |state, path| match state.kind_of(path) {
+            Some(VirtualFileKind::File) => {
+                state.unlink(path);
+                Ok(())
+            }
+            Some(VirtualFileKind::Dir) => Err(errno(libc::EISDIR)),
+            None => Err(errno(libc::ENOENT)),
+        })
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let to = normalize(to);
+        self.namespace(from, |state, from| {
+            let from_kind = state.kind_of(from).ok_or_else(|| errno(libc::ENOENT))?;
+            if from == to {
+                return Ok(());
+            }
+            state.require_parent_dir(&to)?;
+            match (from_kind, state.kind_of(&to)) {
+                (VirtualFileKind::File, Some(VirtualFileKind::Dir)) => {
+                    return Err(errno(libc::EISDIR));
+                }
+                (VirtualFileKind::Dir, Some(VirtualFileKind::File)) => {
+                    return Err(errno(libc::ENOTDIR));
+                }
+                (VirtualFileKind::Dir, Some(VirtualFileKind::Dir)) => {
+                    if !state.children(&to).is_empty() {
+                        return Err(errno(libc::ENOTEMPTY));
+                    }
+                }
+                _ => {}
+            }
+            if from_kind == VirtualFileKind::Dir && to.starts_with(from) {
+                return Err(errno(libc::EINVAL));
+            }
+            state.unlink(&to);
+            let moved: Vec<(PathBuf, u64)> = std::iter::once(from.to_path_buf())
+                .chain(state.descendants(from))
+                .filter_map(|old| {
+                    let id = state.entries.remove(&old)?;
+                    let suffix = old.strip_prefix(from).ok()?.to_path_buf();
+                    let new = if suffix.as_os_str().is_empty() {
+                        to.clone()
+                    } else {
+                        to.join(suffix)
+                    };
+                    Some((new, id))
+                })
+                .collect();
+            for (new, id) in moved {
+                state.entries.insert(new, id);
+            }
+            Ok(())
+        })
+    }
+
+    async fn copy(&self, src: &Path, dst: &Path) -> io::Result<u64> {
+        let src = normalize(src);
+        let dst = normalize(dst);
+        let mut state = self.state.lock();
+        let (latency, failure) = state.begin(SimFsOp::Write, &dst);
+        let src_id = state.lookup(&src)?;
+        let src_inode = state.inode(src_id)?;
+        if src_inode.kind != VirtualFileKind::File {
+            return Err(errno(libc::EISDIR));
+        }
+        let data = src_inode.data.clone();
+        let mode = src_inode.mode;
+        let dst_id = match state.entries.get(&dst).copied() {
+            Some(id) if state.inode(id)?.kind == VirtualFileKind::Dir => {
+                return Err(errno(libc::EISDIR));
+            }
+            Some(id) => id,
+            None => {
+                state.require_parent_dir(&dst)?;
+                let id = state.alloc_inode(VirtualFileKind::File, mode);
+                state.entries.insert(dst.clone(), id);
+                id
+            }
+        };
+        state.inode_mut(dst_id)?.data.clear();
+        state.write_at(dst_id, &dst, 0, &data, latency, failure)?;
+        Ok(data.len() as u64)
+    }
+
+    async fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        let link = normalize(link);
+        self.namespace(original, |state, original| {
+            let id = state.lookup(original)?;
+            if state.inode(id)?.kind == VirtualFileKind::Dir {
+                return Err(errno(libc::EPERM));
+            }
+            if state.entries.contains_key(&link) {
+                return Err(errno(libc::EEXIST));
+            }
+            state.require_parent_dir(&link)?;
+            state.entries.insert(link, id);
+            Ok(())
+        })
+    }
+
+    async fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.namespace(path, |state, path| {
+            state.lookup(path)?;
+            Ok(path.to_path_buf())
+        })
+    }
+
+    async fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.namespace(path, |state, path| {
+            state.lookup(path)?;
+            Err(errno(libc::EINVAL))
+        })
+    }
+
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let path = normalize(path);
+        let mut state = self.state.lock();
+        let (latency, failure) = state.begin(SimFsOp::Read, &path);
+        state.settle(SimFsOp::Read, &path, latency, failure)?;
+        let inode = state.inode(state.lookup(&path)?)?;
+        if inode.kind == VirtualFileKind::Dir {
+            return Err(errno(libc::EISDIR));
+        }
+        Ok(inode.data.clone())
+    }
+
+    async fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let bytes = self.read(path).await?;
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    async fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let path = normalize(path);
+        let mut state = self.state.lock();
+        let (latency, failure) = state.begin(SimFsOp::Write, &path);
+        let id = match state.entries.get(&path).copied() {
+            Some(id) if state.inode(id)?.kind == VirtualFileKind::Dir => {
+                return Err(errno(libc::EISDIR));
+            }
+            Some(id) => id,
+            None => {
+                state.require_parent_dir(&path)?;
+                let id = state.alloc_inode(VirtualFileKind::File, DEFAULT_FILE_MODE);
+                state.entries.insert(path.clone(), id);
+                id
+            }
+        };
+        state.inode_mut(id)?.data.clear();
+        state.write_at(id, &path, 0, contents, latency, failure)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+    use futures_lite::future::block_on;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn p(path: &str) -> &Path {
+        Path::new(path)
+    }
+
+    fn write_opts() -> OpenOptions {
+        OpenOptions::new().write(true).create(true)
+    }
+
+    fn decisions(fs: &SimFs) -> Vec<SimFsDecision> {
+        fs.trace().into_iter().map(|event| event.decision).collect()
+    }
+
+    // -- scenario: resumable copy --
+
+    /// Copies `src` to `dst` in `chunk`-sized pieces. After each chunk is
+    /// synced, the copied length is committed to `checkpoint` with the
+    /// write-temp / fsync / rename pattern. A rerun truncates `dst` back to
+    /// the checkpoint, discarding any torn or unsynced tail, and resumes.
+    async fn resumable_copy(
+        fs: &SimFs,
+        src: &Path,
+        dst: &Path,
+        checkpoint: &Path,
+        chunk: usize,
+    ) -> io::Result<()> {
+        let source = fs.read(src).await?;
+        let mut done = match fs.read_to_string(checkpoint).await {
+            Ok(text) => text.trim().parse::<usize>().unwrap_or(0),
+            Err(err) if err.raw_os_error() == Some(libc::ENOENT) => 0,
+            Err(err) => return Err(err),
+        };
+        let mut out = fs.open(dst, &write_opts()).await?;
+        out.set_len(done as u64).await?;
+        out.seek(SeekFrom::Start(done as u64)).await?;
+        while done < source.len() {
+            let end = (done + chunk).min(source.len());
+            out.write_all(&source[done..end]).await?;
+            out.sync_data().await?;
+            done = end;
+            let staging = checkpoint.with_extension("tmp");
+            let mut marker = fs.open_create(&staging).await?;
+            marker.write_all(done.to_string().as_bytes()).await?;
+            marker.sync_all().await?;
+            fs.rename(&staging, checkpoint).await?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn torn_write_is_recovered_by_resumable_copy() {
+        init_test("torn_write_is_recovered_by_resumable_copy");
+        block_on(async {
+            // Each write to the destination takes 1ms, so the clock reads
+            // 3ms when the third chunk (after set_len and two chunks) starts.
+            let fs = SimFs::new(
+                SimFsConfig::new(7).rule(SimFsRule::new("/data/dst").latency(
+                    SimFsOp::Write,
+                    LatencyModel::Fixed(Duration::from_millis(1)),
+                )),
+            );
+            fs.create_dir_all(p("/data")).await.unwrap();
+            let source: Vec<u8> = (0..=255u8).cycle().take(40).collect();
+            fs.write(p("/data/src"), &source).await.unwrap();
+            fs.schedule(
+                Time::from_millis(3),
+                SimFsFault::Arm {
+                    glob: "/data/dst".into(),
+                    failure: SimFsFailure::TornWrite,
+                    count: 1,
+                },
+            );
+
+            let first = resumable_copy(&fs, p("/data/src"), p("/data/dst"), p("/data/ckpt"), 8)
+                .await
+                .unwrap_err();
+            crate::assert_with_log!(
+                first.raw_os_error() == Some(libc::EIO),
+                "torn write surfaces EIO",
+                Some(libc::EIO),
+                first.raw_os_error()
+            );
+            let torn = fs.read(p("/data/dst")).await.unwrap();
+            crate::assert_with_log!(
+                (16..24).contains(&torn.len()),
+                "torn prefix after two chunks",
+                "16..24",
+                torn.len()
+            );
+            crate::assert_with_log!(
+                torn[..] == source[..torn.len()],
+                "prefix intact",
+                true,
+                torn[..] == source[..torn.len()]
+            );
+
+            // Power loss before the rerun: only synced chunks survive.
+            fs.inject(SimFsFault::Crash);
+            let checkpoint = fs.read_to_string(p("/data/ckpt")).await.unwrap();
+            crate::assert_with_log!(checkpoint == "16", "checkpoint", "16", checkpoint);
+            let len = fs.metadata(p("/data/dst")).await.unwrap().len();
+            crate::assert_with_log!(len == 16, "torn tail discarded", 16, len);
+
+            resumable_copy(&fs, p("/data/src"), p("/data/dst"), p("/data/ckpt"), 8)
+                .await
+                .unwrap();
+            let copied = fs.read(p("/data/dst")).await.unwrap();
+            crate::assert_with_log!(copied == source, "copy complete", source, copied);
+
+            let torn_events = decisions(&fs)
+                .into_iter()
+                .filter(|decision| matches!(decision, SimFsDecision::Torn { .. }))
+                .count();
+            crate::assert_with_log!(torn_events == 1, "torn write traced", 1, torn_events);
+        });
+        crate::test_complete!("torn_write_is_recovered_by_resumable_copy");
+    }
+
+    // -- scenario: spill to disk --
+
+    /// Buffers records in memory and spills them to a file when the buffer
+    /// exceeds `threshold`. A failed spill removes the partial file and keeps
+    /// the records in memory.
+    struct Spill {
+        fs: SimFs,
+        path: PathBuf,
+        threshold: usize,
+        buffer: Vec<u8>,
+        spilled: usize,
+        failed_spills: usize,
+    }
+
+    impl Spill {
+        async fn push(&mut self, record: &[u8]) -> io::Result<()> {
+            self.buffer.extend_from_slice(record);
+            if self.buffer.len() <= self.threshold {
+                return Ok(());
+            }
+            match self.spill().await {
+                Ok(()) => {
+                    self.spilled += self.buffer.len();
+                    self.buffer.clear();
+                    Ok(())
+                }
+                Err(err) if err.raw_os_error() == Some(libc::ENOSPC) => {
+                    self.failed_spills += 1;
+                    match self.fs.remove_file(&self.path).await {
+                        Err(err) if err.raw_os_error() != Some(libc::ENOENT) => Err(err),
+                        _ => Ok(()),
+                    }
+                }
+                Err(err) => Err(err),
+            }
+        }
+
+        async fn spill(&self) -> io::Result<()> {
+            let mut file = self.fs.open_create(&self.path).await?;
+            file.write_all(&self.buffer).await?;
+            file.sync_all().await
+        }
+    }
+
+    #[test]
+    fn enospc_during_spill_keeps_records_in_memory() {
+        init_test("enospc_during_spill_keeps_records_in_memory");
+        block_on(async {
+            let fs = SimFs::new(SimFsConfig::new(11).capacity_bytes(1024));
+            fs.create_dir(p("/spill")).await.unwrap();
+            let mut pressure = FaultEvent {
+                at_ms: 0,
+                action: FaultAction::DiskPressure,
+                args: BTreeMap::new(),
+            };
+            pressure
+                .args
+                .insert("path".into(), serde_json::json!("/spill/**"));
+            pressure.args.insert("bytes".into(), serde_json::json!(1));
+            assert!(fs.apply_scenario_fault(&pressure));
+
+            let mut spill = Spill {
+                fs: fs.clone(),
+                path: PathBuf::from("/spill/run-0"),
+                threshold: 16,
+                buffer: Vec::new(),
+                spilled: 0,
+                failed_spills: 0,
+            };
+            spill.push(&[1; 12]).await.unwrap();
+            spill.push(&[2; 12]).await.unwrap();
+            crate::assert_with_log!(
+                spill.failed_spills == 1,
+                "spill hit ENOSPC",
+                1,
+                spill.failed_spills
+            );
+            crate::assert_with_log!(
+                spill.buffer.len() == 24,
+                "records retained",
+                24,
+                spill.buffer.len()
+            );
+            let leftover = fs.metadata(p("/spill/run-0")).await.is_ok();
+            crate::assert_with_log!(!leftover, "partial spill removed", false, leftover);
+
+            let mut recovered = pressure.clone();
+            recovered.action = FaultAction::DiskRecovered;
+            assert!(fs.apply_scenario_fault(&recovered));
+            spill.push(&[3; 4]).await.unwrap();
+            crate::assert_with_log!(spill.spilled == 28, "spilled", 28, spill.spilled);
+
+            // Capacity is a second ENOSPC source, independent of faults.
+            let err = fs.write(p("/spill/huge"), &[0; 2048]).await.unwrap_err();
+            crate::assert_with_log!(
+                err.kind() == io::ErrorKind::StorageFull,
+                "capacity ENOSPC",
+                io::ErrorKind::StorageFull,
+                err.kind()
+            );
+            crate::assert_with_log!(fs.used_bytes() == 28, "usage", 28, fs.used_bytes());
+        });
+        crate::test_complete!("enospc_during_spill_keeps_records_in_memory");
+    }
+
+    // -- scenario: fsync lie detection --
+
+    /// Appends acknowledged records to a journal, one fsync per record.
+    async fn append_records(fs: &SimFs, journal: &Path, records: &[&str]) -> io::Result<usize> {
+        let opts = OpenOptions::new().append(true).create(true);
+        let mut file = fs.open(journal, &opts).await?;
+        let mut acknowledged = 0;
+        for record in records {
+            file.write_all(format!("{record}\n").as_bytes()).await?;
+            file.sync_data().await?;
+            acknowledged += 1;
+        }
+        Ok(acknowledged)
+    }
+
+    #[test]
+    fn fsync_lie_is_detected_after_crash() {
+        init_test("fsync_lie_is_detected_after_crash");
+        block_on(async {
+            let fs = SimFs::new(SimFsConfig::new(3));
+            let journal = p("/journal.log");
+            let acknowledged = append_records(&fs, journal, &["a", "b"]).await.unwrap();
+
+            fs.inject(SimFsFault::Arm {
+                glob: "/*.log".into(),
+                failure: SimFsFailure::FsyncLie,
+                count: 1,
+            });
+            let acknowledged = acknowledged + append_records(&fs, journal, &["c"]).await.unwrap();
+            crate::assert_with_log!(acknowledged == 3, "all acked", 3, acknowledged);
+
+            fs.inject(SimFsFault::Crash);
+            let recovered = fs.read_to_string(journal).await.unwrap();
+            let durable_records = recovered.lines().count();
+            let lie_detected = durable_records < acknowledged;
+            crate::assert_with_log!(lie_detected, "lost acked record", true, lie_detected);
+            crate::assert_with_log!(recovered == "a\nb\n", "recovered", "a\nb\n", recovered);
+            let lied = decisions(&fs).contains(&SimFsDecision::FsyncLied);
+            crate::assert_with_log!(lied, "lie traced", true, lied);
+        });
+        crate::test_complete!("fsync_lie_is_detected_after_crash");
+    }
+
+    // -- crash semantics --
+
+    #[test]
+    fn crash_discards_unsynced_writes_and_kills_handles() {
+        init_test("crash_discards_unsynced_writes_and_kills_handles");
+        block_on(async {
+            let fs = SimFs::default();
+            fs.create_dir(p("/db")).await.unwrap();
+
+            let mut synced = fs.open(p("/db/synced"), &write_opts()).await.unwrap();
+            synced.write_all(b"durable").await.unwrap();
+            synced.sync_all().await.unwrap();
+            synced.write_all(b"+volatile").await.unwrap();
+
+            fs.write(p("/db/never-synced"), b"gone").await.unwrap();
+
+            let mut staged = fs.open_create(p("/db/config.tmp")).await.unwrap();
+            staged.write_all(b"v2").await.unwrap();
+            staged.sync_all().await.unwrap();
+            drop(staged);
+            fs.rename(p("/db/config.tmp"), p("/db/config"))
+                .await
+                .unwrap();
+
+            crate::assert_with_log!(
+                fs.durable_contents(p("/db/synced")).as_deref() == Some(&b"durable"[..]),
+                "durable view",
+                "durable",
+                fs.durable_contents(p("/db/synced"))
+            );
+
+            fs.schedule(fs.now(), SimFsFault::Crash);
+            let survivors = fs.read(p("/db/synced")).await.unwrap();
+            crate::assert_with_log!(
+                survivors == b"durable",
+                "synced prefix survives",
+                "durable",
+                String::from_utf8_lossy(&survivors)
+            );
+            let never = fs.read(p("/db/never-synced")).await.unwrap();
+            crate::assert_with_log!(never.is_empty(), "unsynced data lost", 0, never.len());
+            let config = fs.read(p("/db/config")).await.unwrap();
+            crate::assert_with_log!(config == b"v2", "renamed file survives", "v2", config);
+
+            let stale = synced.write_all(b"after").await.unwrap_err();
+            crate::assert_with_log!(
+                stale.raw_os_error() == Some(libc::EIO),
+                "pre-crash handle dead",
+                Some(libc::EIO),
+                stale.raw_os_error()
+            );
+
+            let crashed = decisions(&fs)
+                .into_iter()
+                .find_map(|decision| match decision {
+                    SimFsDecision::Crashed {
+                        lost_files,
+                        lost_bytes,
+                    } => Some((lost_files, lost_bytes)),
+                    _ => None,
+                });
+            crate::assert_with_log!(
+                crashed == Some((2, 13)),
+                "crash accounting",
+                Some((2, 13)),
+                crashed
+            );
+        });
+        crate::test_complete!("crash_discards_unsynced_writes_and_kills_handles");
+    }
+
+    #[test]
+    fn vfs_semantics_match_posix_basics() {
+        init_test("vfs_semantics_match_posix_basics");
+        block_on(async {
+            let fs = SimFs::default();
+            fs.create_dir_all(p("/a/b")).await.unwrap();
+            fs.write(p("/a/b/one"), b"1").await.unwrap();
+            fs.write(p("/a/two"), b"22").await.unwrap();
+
+            let missing = fs.write(p("/nope/x"), b"").await.unwrap_err();
+            assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+            let not_empty = fs.remove_dir(p("/a")).await.unwrap_err();
+            assert_eq!(not_empty.raw_os_error(), Some(libc::ENOTEMPTY));
+
+            let mut listing = fs.read_dir(p("/a")).await.unwrap();
+            let mut names = Vec::new();
+            while let Some(entry) = listing.next_entry().await.unwrap() {
+                let is_dir = entry.file_type().await.unwrap().is_dir();
+                names.push((entry.file_name().to_string_lossy().into_owned(), is_dir));
+            }
+            assert_eq!(names, vec![("b".into(), true), ("two".into(), false)]);
+
+            fs.rename(p("/a"), p("/z")).await.unwrap();
+            assert_eq!(fs.read(p("/z/b/one")).await.unwrap(), b"1");
+            fs.hard_link(p("/z/two"), p("/z/link")).await.unwrap();
+            fs.remove_file(p("/z/two")).await.unwrap();
+            assert_eq!(fs.metadata(p("/z/link")).await.unwrap().len(), 2);
+            assert_eq!(fs.copy(p("/z/link"), p("/z/copy")).await.unwrap(), 2);
+
+            let mut file = fs
+                .open(p("/z/copy"), &OpenOptions::new().read(true).write(true))
+                .await
+                .unwrap();
+            file.seek(SeekFrom::End(0)).await.unwrap();
+            file.write_all(b"3").await.unwrap();
+            file.seek(SeekFrom::Start(0)).await.unwrap();
+            let mut text = String::new();
+            file.read_to_string(&mut text).await.unwrap();
+            assert_eq!(text, "223");
+
+            let dup = fs
+                .open(p("/z/copy"), &write_opts().create_new(true))
+                .await
+                .unwrap_err();
+            assert_eq!(dup.kind(), io::ErrorKind::AlreadyExists);
+            assert_eq!(
+                fs.canonicalize(p("/z/b/../copy")).await.unwrap(),
+                PathBuf::from("/z/copy")
+            );
+            fs.remove_dir_all(p("/z")).await.unwrap();
+            assert_eq!(fs.used_bytes(), 0);
+        });
+        crate::test_complete!("vfs_semantics_match_posix_basics");
+    }
+
+    // -- latency, probabilistic faults, replay --
+
+    #[test]
+    fn latency_follows_glob_rules_in_virtual_time() {
+        init_test("latency_follows_glob_rules_in_virtual_time");
+        block_on(async {
+            let fs = SimFs::new(
+                SimFsConfig::new(1)
+                    .rule(
+                        SimFsRule::new("/slow/**")
+                            .latency(
+                                SimFsOp::Write,
+                                LatencyModel::Fixed(Duration::from_millis(5)),
+                            )
+                            .latency(
+                                SimFsOp::Fsync,
+                                LatencyModel::Fixed(Duration::from_millis(20)),
+                            ),
+                    )
+                    .rule(SimFsRule::new("**").latency(
+                        SimFsOp::Write,
+                        LatencyModel::Fixed(Duration::from_millis(1)),
+                    )),
+            );
+            fs.create_dir(p("/slow")).await.unwrap();
+            fs.write(p("/fast"), b"x").await.unwrap();
+            assert_eq!(fs.now(), Time::from_millis(1));
+
+            let file = {
+                let mut file = fs.open_create(p("/slow/f")).await.unwrap();
+                file.write_all(b"y").await.unwrap();
+                file
+            };
+            assert_eq!(fs.now(), Time::from_millis(6));
+            file.sync_all().await.unwrap();
+            assert_eq!(fs.now(), Time::from_millis(26));
+
+            let fsync = fs
+                .trace()
+                .into_iter()
+                .find(|event| event.op == Some(SimFsOp::Fsync))
+                .unwrap();
+            assert_eq!(fsync.latency, Duration::from_millis(20));
+            assert_eq!(fsync.time, Time::from_millis(26));
+        });
+        crate::test_complete!("latency_follows_glob_rules_in_virtual_time");
+    }
+
+    #[test]
+    fn seeded_faults_replay_identically() {
+        init_test("seeded_faults_replay_identically");
+        let run = |seed: u64| {
+            block_on(async move {
+                let fs = SimFs::new(
+                    SimFsConfig::new(seed).rule(
+                        SimFsRule::new("/**")
+                            .latency(
+                                SimFsOp::Write,
+                                LatencyModel::Uniform {
+                                    min: Duration::from_micros(10),
+                                    max: Duration::from_millis(2),
+                                },
+                            )
+                            .fail(SimFsFailure::Io(SimFsOp::Write), 0.2)
+                            .fail(SimFsFailure::TornWrite, 0.2)
+                            .fail(SimFsFailure::FsyncLie, 0.3),
+                    ),
+                );
+                let mut file = fs.open_create(p("/f")).await.unwrap();
+                for i in 0..32u8 {
+                    let _ = file.write(&[i; 16]).await;
+                    let _ = file.sync_data().await;
+                }
+                (fs.trace(), fs.read(p("/f")).await.unwrap())
+            })
+        };
+        let (trace_a, data_a) = run(99);
+        let (trace_b, data_b) = run(99);
+        assert_eq!(trace_a, trace_b);
+        assert_eq!(data_a, data_b);
+        let injected = trace_a
+            .iter()
+            .filter(|event| event.decision != SimFsDecision::Proceeded)
+            .count();
+        assert!(injected > 0, "seeded rules should inject failures");
+        let (trace_c, _) = run(100);
+        assert_ne!(trace_a, trace_c, "different seeds should diverge");
+        crate::test_complete!("seeded_faults_replay_identically");
+    }
+
+    #[test]
+    fn glob_matching_is_component_aware() {
+        assert!(glob_match("/data/**", p("/data/a/b")));
+        assert!(glob_match("/data/**", p("/data")));
+        assert!(glob_match("**/*.log", p("/x/y/z.log")));
+        assert!(glob_match("/d?ta/*", p("/data/f")));
+        assert!(!glob_match("/data/*", p("/data/a/b")));
+        assert!(!glob_match("/data/*.log", p("/data/x.txt")));
+    }
+}
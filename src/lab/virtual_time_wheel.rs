@@ -5,7 +5,9 @@
 //! by ensuring:
 //!
 //! - Same tick → same timers expire
-//! - Expiration order is deterministic (sorted by timer ID)
+//! - Expiration order is deterministic: by deadline, then [`TimerClass`],
+//!   then timer ID — the same order the production
+//!   [`TimerWheel`](crate::time::TimerWheel) dispatches in
 //! - No wall-clock dependencies
 //!
 //! # Example
@@ -29,6 +31,7 @@
 //! assert_eq!(expired.len(), 1);  // waker1 expired
 //! ```
 
+use crate::time::TimerClass;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::task::Waker;
@@ -38,6 +41,8 @@ use std::task::Waker;
 struct VirtualTimer {
     /// Deadline in virtual ticks.
     deadline: u64,
+    /// Priority class among timers sharing a deadline.
+    class: TimerClass,
     /// Unique timer ID for deterministic ordering.
     timer_id: u64,
     /// Waker to call when the timer expires.
@@ -54,10 +59,12 @@ impl PartialEq for VirtualTimer {
 
 impl Ord for VirtualTimer {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Min-heap ordering: earliest deadline first, then lowest timer_id
+        // Min-heap ordering: earliest deadline first, then most urgent class,
+        // then lowest timer_id
         other
             .deadline
             .cmp(&self.deadline)
+            .then_with(|| other.class.cmp(&self.class))
             .then_with(|| other.timer_id.cmp(&self.timer_id))
     }
 }
@@ -98,6 +105,8 @@ pub struct ExpiredTimer {
     pub timer_id: u64,
     /// Deadline tick when the timer was set to expire.
     pub deadline: u64,
+    /// Priority class the timer was registered with.
+    pub class: TimerClass,
     /// Waker to wake the waiting task.
     pub waker: Waker,
 }
@@ -110,7 +119,7 @@ pub struct ExpiredTimer {
 /// # Determinism Guarantees
 ///
 /// - Same tick → same timers expire (deadlines are stored as u64 ticks)
-/// - Expiration order is deterministic (sorted by timer ID within same tick)
+/// - Expiration order is deterministic (by class, then timer ID within same tick)
 /// - No wall-clock dependencies (uses heap for simplicity and correctness)
 #[derive(Debug, Default)]
 pub struct VirtualTimerWheel {
@@ -180,6 +189,19 @@ impl VirtualTimerWheel {
     ///
     /// Returns a handle that can be used to cancel the timer.
     pub fn insert(&mut self, deadline: u64, waker: Waker) -> VirtualTimerHandle {
+        self.insert_with_class(deadline, waker, TimerClass::Normal)
+    }
+
+    /// Inserts a timer in the given priority class.
+    ///
+    /// Among timers sharing a deadline tick, lower classes expire first and
+    /// timers of the same class expire in insertion order.
+    pub fn insert_with_class(
+        &mut self,
+        deadline: u64,
+        waker: Waker,
+        class: TimerClass,
+    ) -> VirtualTimerHandle {
         let deadline = deadline.max(self.current_tick);
         let timer_id = self.next_timer_id;
         self.next_timer_id = self
@@ -189,6 +211,7 @@ impl VirtualTimerWheel {
 
         self.heap.push(VirtualTimer {
             deadline,
+            class,
             timer_id,
             waker,
         });
@@ -223,8 +246,8 @@ impl VirtualTimerWheel {
 
     /// Advances virtual time to the next timer deadline.
     ///
-    /// Returns the list of expired timers in deterministic order (by timer_id).
-    /// If there are no pending timers, returns an empty list and does not
+    /// Returns the list of expired timers in deterministic order (by class,
+    /// then timer_id). If there are no pending timers, returns an empty list and does not
     /// advance time.
     pub fn advance_to_next(&mut self) -> Vec<ExpiredTimer> {
         self.next_deadline()
@@ -233,7 +256,8 @@ impl VirtualTimerWheel {
 
     /// Advances virtual time by the given number of ticks.
     ///
-    /// Returns all expired timers in deterministic order (by timer_id).
+    /// Returns all expired timers in deterministic order (by deadline, class,
+    /// then timer_id).
     pub fn advance_by(&mut self, ticks: u64) -> Vec<ExpiredTimer> {
         self.advance_to(self.current_tick.saturating_add(ticks))
     }
@@ -241,7 +265,7 @@ impl VirtualTimerWheel {
     /// Advances to the given absolute tick, processing all timers up to that point.
     ///
     /// Returns all expired timers in deterministic order (sorted by deadline,
-    /// then by timer_id within each deadline).
+    /// then by class and timer_id within each deadline).
     pub fn advance_to(&mut self, target_tick: u64) -> Vec<ExpiredTimer> {
         if target_tick < self.current_tick {
             return Vec::new();
@@ -267,6 +291,7 @@ impl VirtualTimerWheel {
             expired.push(ExpiredTimer {
                 timer_id: timer.timer_id,
                 deadline: timer.deadline,
+                class: timer.class,
                 waker: timer.waker,
             });
         }
@@ -279,12 +304,8 @@ impl VirtualTimerWheel {
             self.cleanup_cancelled();
         }
 
-        // Sort by deadline first, then by timer_id for determinism
-        expired.sort_by(|a, b| {
-            a.deadline
-                .cmp(&b.deadline)
-                .then_with(|| a.timer_id.cmp(&b.timer_id))
-        });
+        // Sort by deadline first, then by class and timer_id for determinism
+        expired.sort_by_key(|t| (t.deadline, t.class, t.timer_id));
 
         expired
     }
@@ -336,11 +357,7 @@ impl VirtualTimerWheel {
             .iter()
             .filter(|t| t.deadline <= up_to_tick && !self.cancelled.contains(&t.timer_id))
             .collect();
-        ready.sort_by(|a, b| {
-            a.deadline
-                .cmp(&b.deadline)
-                .then_with(|| a.timer_id.cmp(&b.timer_id))
-        });
+        ready.sort_by_key(|t| (t.deadline, t.class, t.timer_id));
         ready.into_iter().map(|t| t.waker.clone()).collect()
     }
 
@@ -770,6 +787,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn same_tick_expiry_orders_by_class_then_timer_id() {
+        let mut wheel = VirtualTimerWheel::new();
+        let wake_order = Arc::new(Mutex::new(Vec::new()));
+
+        let classes = [
+            TimerClass::BestEffort,
+            TimerClass::Normal,
+            TimerClass::Critical,
+            TimerClass::High,
+            TimerClass::Critical,
+            TimerClass::Normal,
+        ];
+        for (id, class) in classes.into_iter().enumerate() {
+            wheel.insert_with_class(100, recording_waker(id, wake_order.clone()), class);
+        }
+        wheel.insert(50, recording_waker(6, wake_order.clone()));
+
+        let collected = wheel.collect_wakers(100);
+        let expired = wheel.advance_to(100);
+        let ids: Vec<u64> = expired.iter().map(|t| t.timer_id).collect();
+        assert_eq!(ids, vec![6, 2, 4, 3, 1, 5, 0]);
+        assert_eq!(expired[1].class, TimerClass::Critical);
+
+        for waker in collected {
+            waker.wake();
+        }
+        let order = wake_order.lock().expect("wake order lock").clone();
+        assert_eq!(order, vec![6, 2, 4, 3, 1, 5, 0]);
+    }
+
     #[test]
     fn virtual_timer_handle_debug_clone_copy_eq_hash() {
         use std::collections::HashSet;
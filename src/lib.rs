@@ -243,6 +243,8 @@ pub mod process;
 pub mod server;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod signal;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod store;
 
 // ── Test-only modules ───────────────────────────────────────────────────
 #[cfg(all(test, feature = "legacy-internal-test-harnesses"))]
@@ -26,16 +26,50 @@
 //! - **EXIT-MONOTONE**: Exit signal reasons cannot downgrade severity
 //!   (e.g., a Panicked reason cannot become an Error during propagation).
 //!
+//! # Trapping Exits
+//!
+//! A task traps exits by setting [`Cx::trap_exits`](crate::Cx::trap_exits).
+//! The flag lives only on the task's `Cx`: when a task completes, the runtime
+//! resolves its links ([`RuntimeState::link_tasks`]) and reads each
+//! partner's flag at that moment. A trapping partner is not cancelled; every
+//! termination, normal or not, becomes an [`ExitSignal`] delivered to the
+//! partner's designated [`ExitMailbox`]. Trapping overrides a per-link
+//! [`ExitPolicy::Propagate`]; an explicit [`ExitPolicy::Ignore`] still wins.
+//! A `LinkSet` driven directly receives the same answer through the `traps`
+//! predicate of [`LinkSet::task_exited`].
+//!
+//! - **EXIT-KILL**: A partner terminated by an explicit kill
+//!   ([`RuntimeState::kill_task`], [`LinkSet::task_killed`]) cancels its
+//!   propagating and trapping partners alike. Kill signals are never
+//!   trappable.
+//! - **EXIT-DEAD**: Linking to a task that has already exited does not create
+//!   a live link. The exit signal the link would have produced is queued
+//!   immediately and returned by [`LinkSet::take_pending_exits`].
+//! - **EXIT-AFTER-MSG**: When the trapping task's mailbox is the channel it
+//!   receives ordinary messages on ([`ExitMailbox::from_sender`]), the exit
+//!   signal is enqueued after every message the dead task sent before
+//!   terminating.
+//!
+//! # Links and Monitors
+//!
+//! A task may both link to and monitor the same partner. The two are
+//! independent: when the partner exits, the link produces exactly one exit
+//! action (per [`LinkRef`]) and the monitor exactly one `Down` notification
+//! (per [`MonitorRef`](crate::monitor::MonitorRef)). Removing one never
+//! removes or suppresses the other.
+//!
 //! # Bead
 //!
 //! bd-k4kmq | Parent: bd-pr46z
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::monitor::DownReason;
 use crate::types::cancel::{CancelKind, CancelReason};
-use crate::types::{RegionId, TaskId, Time};
+use crate::runtime::RuntimeState;
+use crate::types::{RegionId, StaleId, TaskId, Time};
 
 // ============================================================================
 // ExitPolicy — per-link exit signal handling
@@ -161,6 +195,107 @@ pub struct ExitSignal {
     pub link_ref: LinkRef,
 }
 
+// ============================================================================
+// ExitMailbox
+// ============================================================================
+
+/// Designated destination for exit signals trapped by a task.
+///
+/// [`LinkSet::dispatch`] hands each [`LinkExitAction::DeliverExit`] to the
+/// receiving task's mailbox instead of returning it to the caller. The
+/// runtime routes them through [`ExitDeliveries`] so the mailbox runs after
+/// the runtime-state lock is released.
+#[derive(Clone)]
+pub struct ExitMailbox {
+    deliver: Arc<dyn Fn(ExitSignal) -> bool + Send + Sync>,
+}
+
+impl ExitMailbox {
+    /// Delivers exit signals into an mpsc channel without blocking.
+    ///
+    /// Using the channel the task already receives its messages on keeps
+    /// exit signals ordered after the dead task's last message
+    /// (**EXIT-AFTER-MSG**).
+    #[must_use]
+    pub fn from_sender<M>(sender: crate::channel::mpsc::Sender<M>) -> Self
+    where
+        M: From<ExitSignal> + Send + 'static,
+    {
+        Self::from_fn(move |signal| sender.try_send(M::from(signal)).is_ok())
+    }
+
+    /// Delivers exit signals through `deliver`, which returns `false` when
+    /// the signal could not be enqueued.
+    #[must_use]
+    pub fn from_fn<F>(deliver: F) -> Self
+    where
+        F: Fn(ExitSignal) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            deliver: Arc::new(deliver),
+        }
+    }
+
+    /// Attempts to enqueue `signal`. Returns `true` on success.
+    #[must_use]
+    pub fn deliver(&self, signal: ExitSignal) -> bool {
+        (self.deliver)(signal)
+    }
+}
+
+impl std::fmt::Debug for ExitMailbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExitMailbox").finish_non_exhaustive()
+    }
+}
+
+/// Trapped exit signals resolved under a lock, delivered once it is released.
+///
+/// Returned by [`LinkSet::route`]. Mailboxes run arbitrary code (a channel
+/// send wakes its receiver), so callers holding the runtime-state lock
+/// collect deliveries first and call [`dispatch`](Self::dispatch) afterwards.
+#[derive(Debug, Default)]
+#[must_use = "exit deliveries must be dispatched after releasing outer locks"]
+pub struct ExitDeliveries {
+    entries: Vec<(ExitMailbox, ExitSignal)>,
+}
+
+impl ExitDeliveries {
+    /// Returns the number of pending deliveries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is nothing to deliver.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends `other`'s deliveries after this batch's.
+    pub fn append(&mut self, mut other: Self) {
+        self.entries.append(&mut other.entries);
+    }
+
+    /// Delivers every signal in order and returns how many mailboxes
+    /// refused theirs.
+    pub fn dispatch(self) -> usize {
+        let mut refused = 0;
+        for (mailbox, signal) in self.entries {
+            let from = signal.from;
+            if !mailbox.deliver(signal) {
+                refused += 1;
+                crate::tracing_compat::warn!(
+                    from = ?from,
+                    "exit mailbox refused a trapped exit signal"
+                );
+            }
+        }
+        refused
+    }
+}
+
 // ============================================================================
 // LinkExitAction (bd-khkw7)
 // ============================================================================
@@ -305,14 +440,53 @@ struct LinkRecord {
 /// Since links are bidirectional, each link appears in `task_index` for both
 /// task_a and task_b, and in `region_index` for both region_a and region_b
 /// (unless they share a region).
+///
+/// Tasks reported through [`task_exited`](Self::task_exited) or
+/// [`task_killed`](Self::task_killed) are remembered until their region is
+/// cleaned up, so a later link to them resolves immediately (**EXIT-DEAD**).
+/// At most [`EXITED_CAPACITY`](Self::EXITED_CAPACITY) exits are remembered;
+/// the oldest are forgotten first, so a long-lived region does not
+/// accumulate one tombstone per completed task.
+///
+/// The set does not record which tasks trap exits; that is the task's
+/// [`Cx::trap_exits`](crate::Cx::trap_exits) flag, supplied by the caller
+/// when exits are resolved.
 #[derive(Debug, Default)]
 pub struct LinkSet {
     records: BTreeMap<LinkRef, LinkRecord>,
     task_index: BTreeMap<TaskId, Vec<LinkRef>>,
     region_index: BTreeMap<RegionId, Vec<LinkRef>>,
+    mailboxes: BTreeMap<TaskId, ExitMailbox>,
+    exited: BTreeMap<TaskId, ExitedTask>,
+    exited_order: VecDeque<TaskId>,
+    killed: BTreeSet<TaskId>,
+    pending: Vec<DeadPeerExit>,
+}
+
+/// Tombstone for a task whose exit has been resolved.
+#[derive(Debug, Clone)]
+struct ExitedTask {
+    region: RegionId,
+    exit_vt: Time,
+    reason: DownReason,
+    killed: bool,
+}
+
+/// Exit owed to a peer that linked to an already-exited task, resolved when
+/// taken so the peer's trap flag is read at delivery time.
+#[derive(Debug, Clone)]
+struct DeadPeerExit {
+    dead_task: TaskId,
+    dead: ExitedTask,
+    peer: TaskId,
+    policy: ExitPolicy,
+    link_ref: LinkRef,
 }
 
 impl LinkSet {
+    /// Maximum number of exited tasks remembered for **EXIT-DEAD**.
+    pub const EXITED_CAPACITY: usize = 4096;
+
     /// Creates an empty link set.
     #[must_use]
     pub fn new() -> Self {
@@ -342,11 +516,40 @@ impl LinkSet {
         )
     }
 
+    /// Establishes a default link after checking that every id still names a
+    /// live entity in `state`.
+    ///
+    /// A stale id would otherwise link to whichever task now occupies the
+    /// reused slot, or fire an immediate exit for a task that is long gone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] for the first id that no longer names a live task
+    /// or region; no link is created.
+    pub fn establish_checked(
+        &mut self,
+        state: &RuntimeState,
+        task_a: TaskId,
+        region_a: RegionId,
+        task_b: TaskId,
+        region_b: RegionId,
+    ) -> Result<LinkRef, StaleId> {
+        state.checked_task(task_a)?;
+        state.checked_region(region_a)?;
+        state.checked_task(task_b)?;
+        state.checked_region(region_b)?;
+        Ok(self.establish(task_a, region_a, task_b, region_b))
+    }
+
     /// Establishes a bidirectional link with per-side exit policies.
     ///
     /// `policy_a` controls what happens to task_a when task_b terminates
     /// abnormally (and vice versa for `policy_b`).
     ///
+    /// If either task has already exited, no live link is created; the exit
+    /// is queued for [`take_pending_exits`](Self::take_pending_exits) and the
+    /// returned `LinkRef` identifies it (**EXIT-DEAD**).
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -373,6 +576,36 @@ impl LinkSet {
         } else {
             (policy_a, policy_b)
         };
+
+        // EXIT-DEAD: a link to an exited task fires at once instead of
+        // becoming a live link that can never fire.
+        match (self.exited.get(&task_a), self.exited.get(&task_b)) {
+            (None, None) => {}
+            (Some(_), Some(_)) => return link_ref,
+            (None, Some(dead)) => {
+                let dead = dead.clone();
+                self.pending.push(DeadPeerExit {
+                    dead_task: task_b,
+                    dead,
+                    peer: task_a,
+                    policy: policy_a,
+                    link_ref,
+                });
+                return link_ref;
+            }
+            (Some(dead), None) => {
+                let dead = dead.clone();
+                self.pending.push(DeadPeerExit {
+                    dead_task: task_a,
+                    dead,
+                    peer: task_b,
+                    policy: policy_b,
+                    link_ref,
+                });
+                return link_ref;
+            }
+        }
+
         let record = LinkRecord {
             task_a,
             region_a,
@@ -477,6 +710,9 @@ impl LinkSet {
     /// established by tasks in that region are released. No further
     /// exit signals are delivered to tasks in the region.
     pub fn cleanup_region(&mut self, region: RegionId) -> Vec<LinkRef> {
+        self.exited.retain(|_, dead| dead.region != region);
+        let exited = &self.exited;
+        self.exited_order.retain(|task| exited.contains_key(task));
         let Some(refs) = self.region_index.remove(&region) else {
             return Vec::new();
         };
@@ -597,6 +833,9 @@ impl LinkSet {
     /// - [`ExitPolicy::Trap`] → [`LinkExitAction::DeliverExit`]
     /// - [`ExitPolicy::Ignore`] → [`LinkExitAction::Ignored`]
     ///
+    /// Only link policies are consulted here; [`task_exited`](Self::task_exited)
+    /// also honors partners that trap exits.
+    ///
     /// The returned [`LinkExitBatch`] is sorted deterministically.
     #[must_use]
     pub fn resolve_exits(
@@ -605,11 +844,173 @@ impl LinkSet {
         exit_vt: Time,
         reason: &DownReason,
     ) -> LinkExitBatch {
-        // OTP semantics: normal completion silently removes links.
-        if matches!(reason, DownReason::Normal) {
-            return LinkExitBatch::new();
+        self.resolve(crashed_task, exit_vt, reason, false, &|_| false)
+    }
+
+    /// Like [`resolve_exits`](Self::resolve_exits) for a task terminated by
+    /// an explicit kill: partners that would trap the exit are cancelled
+    /// instead (**EXIT-KILL**).
+    #[must_use]
+    pub fn resolve_kill(
+        &self,
+        killed_task: TaskId,
+        exit_vt: Time,
+        reason: &DownReason,
+    ) -> LinkExitBatch {
+        self.resolve(killed_task, exit_vt, reason, true, &|_| false)
+    }
+
+    /// Records that `task` (owned by `region`) terminated, resolves its exit
+    /// signals, and releases its links and exit mailbox.
+    ///
+    /// `traps` reports whether a partner traps exits; the runtime answers it
+    /// from the partner's [`Cx::trap_exits`](crate::Cx::trap_exits) flag.
+    /// A task marked with [`mark_killed`](Self::mark_killed) exits as
+    /// [`task_killed`](Self::task_killed) would.
+    ///
+    /// The task is remembered until `region` is cleaned up so links
+    /// established to it later fire immediately (**EXIT-DEAD**).
+    pub fn task_exited(
+        &mut self,
+        task: TaskId,
+        region: RegionId,
+        exit_vt: Time,
+        reason: DownReason,
+        traps: impl Fn(TaskId) -> bool,
+    ) -> LinkExitBatch {
+        let killed = self.killed.remove(&task);
+        self.retire(task, region, exit_vt, reason, killed, &traps)
+    }
+
+    /// Like [`task_exited`](Self::task_exited) for a task terminated by an
+    /// explicit kill. Kill signals are never trappable (**EXIT-KILL**).
+    pub fn task_killed(
+        &mut self,
+        task: TaskId,
+        region: RegionId,
+        exit_vt: Time,
+        reason: DownReason,
+        traps: impl Fn(TaskId) -> bool,
+    ) -> LinkExitBatch {
+        self.killed.remove(&task);
+        self.retire(task, region, exit_vt, reason, true, &traps)
+    }
+
+    /// Marks `task` as killed, so its eventual exit is untrappable.
+    ///
+    /// Returns `false` if the task was already marked.
+    pub fn mark_killed(&mut self, task: TaskId) -> bool {
+        self.killed.insert(task)
+    }
+
+    /// Returns `true` if `task` has exited and not yet been cleaned up.
+    #[must_use]
+    pub fn has_exited(&self, task: TaskId) -> bool {
+        self.exited.contains_key(&task)
+    }
+
+    /// Returns the region recorded for `task` if it has exited and not yet
+    /// been cleaned up.
+    #[must_use]
+    pub fn exited_region(&self, task: TaskId) -> Option<RegionId> {
+        self.exited.get(&task).map(|dead| dead.region)
+    }
+
+    /// Designates the mailbox that receives `task`'s trapped exit signals.
+    ///
+    /// Returns the previously designated mailbox.
+    pub fn set_exit_mailbox(&mut self, task: TaskId, mailbox: ExitMailbox) -> Option<ExitMailbox> {
+        self.mailboxes.insert(task, mailbox)
+    }
+
+    /// Removes `task`'s designated exit mailbox.
+    pub fn clear_exit_mailbox(&mut self, task: TaskId) -> Option<ExitMailbox> {
+        self.mailboxes.remove(&task)
+    }
+
+    /// Takes the exit actions queued by links established to tasks that had
+    /// already exited, resolving each against `traps` now.
+    #[must_use]
+    pub fn take_pending_exits(&mut self, traps: impl Fn(TaskId) -> bool) -> LinkExitBatch {
+        let mut batch = LinkExitBatch::new();
+        for owed in std::mem::take(&mut self.pending) {
+            let DeadPeerExit {
+                dead_task,
+                dead,
+                peer,
+                policy,
+                link_ref,
+            } = owed;
+            if let Some(action) = Self::exit_action(
+                dead_task,
+                peer,
+                policy,
+                &dead.reason,
+                link_ref,
+                dead.killed,
+                traps(peer),
+            ) {
+                batch.push(dead.exit_vt, dead_task, peer, link_ref, action);
+            }
+        }
+        batch
+    }
+
+    /// Delivers a batch in deterministic order.
+    ///
+    /// Each [`LinkExitAction::DeliverExit`] is handed to the receiver's
+    /// designated [`ExitMailbox`]. Actions that remain for the caller are
+    /// returned in order: cancellations, ignored exits, and exits whose
+    /// receiver has no mailbox or whose mailbox refused the signal.
+    #[must_use]
+    pub fn dispatch(&self, batch: LinkExitBatch) -> Vec<LinkExitAction> {
+        batch
+            .into_sorted()
+            .into_iter()
+            .filter(|action| match action {
+                LinkExitAction::DeliverExit { to, signal } => !self
+                    .mailboxes
+                    .get(to)
+                    .is_some_and(|mailbox| mailbox.deliver(signal.clone())),
+                LinkExitAction::CancelPeer { .. } | LinkExitAction::Ignored { .. } => true,
+            })
+            .collect()
+    }
+
+    /// Splits a batch, in deterministic order, into mailbox deliveries and
+    /// the actions left for the caller.
+    ///
+    /// Unlike [`dispatch`](Self::dispatch) this runs no mailbox code: trapped
+    /// exits whose receiver has a mailbox are returned as [`ExitDeliveries`];
+    /// everything else, including trapped exits with no mailbox, is returned
+    /// as actions.
+    pub fn route(&self, batch: LinkExitBatch) -> (ExitDeliveries, Vec<LinkExitAction>) {
+        let mut deliveries = ExitDeliveries::default();
+        let mut actions = Vec::new();
+        for action in batch.into_sorted() {
+            match action {
+                LinkExitAction::DeliverExit { to, signal } => match self.mailboxes.get(&to) {
+                    Some(mailbox) => deliveries.entries.push((mailbox.clone(), signal)),
+                    None => actions.push(LinkExitAction::DeliverExit { to, signal }),
+                },
+                other @ (LinkExitAction::CancelPeer { .. } | LinkExitAction::Ignored { .. }) => {
+                    actions.push(other);
+                }
+            }
         }
+        (deliveries, actions)
+    }
 
+    // -- private helpers --
+
+    fn resolve(
+        &self,
+        crashed_task: TaskId,
+        exit_vt: Time,
+        reason: &DownReason,
+        killed: bool,
+        traps: &dyn Fn(TaskId) -> bool,
+    ) -> LinkExitBatch {
         let mut batch = LinkExitBatch::new();
         let Some(refs) = self.task_index.get(&crashed_task) else {
             return batch;
@@ -623,31 +1024,82 @@ impl LinkSet {
             } else {
                 (rec.task_a, rec.policy_a)
             };
-            let action = match policy {
-                ExitPolicy::Propagate => LinkExitAction::CancelPeer {
-                    to: peer,
-                    reason: linked_exit_cancel_reason(reason),
-                    link_ref: *lref,
-                },
-                ExitPolicy::Trap => LinkExitAction::DeliverExit {
-                    to: peer,
-                    signal: ExitSignal {
-                        from: crashed_task,
-                        reason: reason.clone(),
-                        link_ref: *lref,
-                    },
-                },
-                ExitPolicy::Ignore => LinkExitAction::Ignored {
-                    to: peer,
-                    link_ref: *lref,
-                },
-            };
-            batch.push(exit_vt, crashed_task, peer, *lref, action);
+            if let Some(action) =
+                Self::exit_action(crashed_task, peer, policy, reason, *lref, killed, traps(peer))
+            {
+                batch.push(exit_vt, crashed_task, peer, *lref, action);
+            }
         }
         batch
     }
 
-    // -- private helpers --
+    /// Decides what `peer` experiences when `from` exits over `link_ref`.
+    fn exit_action(
+        from: TaskId,
+        peer: TaskId,
+        policy: ExitPolicy,
+        reason: &DownReason,
+        link_ref: LinkRef,
+        killed: bool,
+        traps: bool,
+    ) -> Option<LinkExitAction> {
+        // OTP semantics: normal completion silently removes links unless the
+        // peer traps exits, in which case it observes every termination.
+        if matches!(reason, DownReason::Normal) && !(traps && !policy.is_ignore()) {
+            return None;
+        }
+        let action = match policy {
+            ExitPolicy::Ignore => LinkExitAction::Ignored { to: peer, link_ref },
+            ExitPolicy::Trap | ExitPolicy::Propagate if killed => LinkExitAction::CancelPeer {
+                to: peer,
+                reason: linked_exit_cancel_reason(reason),
+                link_ref,
+            },
+            ExitPolicy::Propagate if !traps => LinkExitAction::CancelPeer {
+                to: peer,
+                reason: linked_exit_cancel_reason(reason),
+                link_ref,
+            },
+            ExitPolicy::Trap | ExitPolicy::Propagate => LinkExitAction::DeliverExit {
+                to: peer,
+                signal: ExitSignal {
+                    from,
+                    reason: reason.clone(),
+                    link_ref,
+                },
+            },
+        };
+        Some(action)
+    }
+
+    fn retire(
+        &mut self,
+        task: TaskId,
+        region: RegionId,
+        exit_vt: Time,
+        reason: DownReason,
+        killed: bool,
+        traps: &dyn Fn(TaskId) -> bool,
+    ) -> LinkExitBatch {
+        let batch = self.resolve(task, exit_vt, &reason, killed, traps);
+        self.remove_task(task);
+        self.mailboxes.remove(&task);
+        let tombstone = ExitedTask {
+            region,
+            exit_vt,
+            reason,
+            killed,
+        };
+        if self.exited.insert(task, tombstone).is_none() {
+            self.exited_order.push_back(task);
+        }
+        while self.exited_order.len() > Self::EXITED_CAPACITY {
+            if let Some(evicted) = self.exited_order.pop_front() {
+                self.exited.remove(&evicted);
+            }
+        }
+        batch
+    }
 
     fn remove_from_task_index(&mut self, task: TaskId, link_ref: LinkRef) {
         if let Some(refs) = self.task_index.get_mut(&task) {
@@ -668,7 +1120,7 @@ impl LinkSet {
     }
 }
 
-fn linked_exit_cancel_reason(exit_reason: &DownReason) -> CancelReason {
+pub(crate) fn linked_exit_cancel_reason(exit_reason: &DownReason) -> CancelReason {
     let base = CancelReason::new(CancelKind::LinkedExit).with_message("link exit");
     match exit_reason {
         DownReason::Cancelled(r) => base.with_cause(r.clone()),
@@ -1945,4 +2397,250 @@ mod tests {
         assert!(set.peers_with_policy(t1).is_empty());
         assert!(set.peers_with_policy(t2).is_empty());
     }
+
+    // ── Trapped exits ──────────────────────────────────────────────────
+
+    fn collecting_mailbox() -> (ExitMailbox, Arc<parking_lot::Mutex<Vec<ExitSignal>>>) {
+        let inbox = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&inbox);
+        let mailbox = ExitMailbox::from_fn(move |signal| {
+            sink.lock().push(signal);
+            true
+        });
+        (mailbox, inbox)
+    }
+
+    #[test]
+    fn trap_exits_delivers_to_mailbox_instead_of_cancelling() {
+        let r = test_region_id(0, 0);
+        let crash = DownReason::Error("boom".into());
+
+        // Trapping off: the partner is cancelled.
+        let mut set = LinkSet::new();
+        let (owner, worker) = (test_task_id(1, 0), test_task_id(2, 0));
+        set.establish(owner, r, worker, r);
+        let actions = set.dispatch(set.resolve_exits(worker, Time::ZERO, &crash));
+        assert!(matches!(
+            actions.as_slice(),
+            [LinkExitAction::CancelPeer { to, .. }] if *to == owner
+        ));
+
+        // Trapping on: the exit becomes a message and nothing is cancelled.
+        let mut set = LinkSet::new();
+        let lref = set.establish(owner, r, worker, r);
+        let (mailbox, inbox) = collecting_mailbox();
+        let traps = |task| task == owner;
+        assert!(set.set_exit_mailbox(owner, mailbox).is_none());
+        let batch = set.task_exited(worker, r, Time::from_nanos(7), crash.clone(), traps);
+        assert!(set.dispatch(batch).is_empty());
+        assert_eq!(
+            inbox.lock().as_slice(),
+            &[ExitSignal {
+                from: worker,
+                reason: crash,
+                link_ref: lref,
+            }]
+        );
+        assert!(set.is_empty());
+
+        // Trapping tasks observe normal exits too; non-trapping ones do not.
+        let other = test_task_id(3, 0);
+        set.establish(owner, r, other, r);
+        let watcher = test_task_id(4, 0);
+        set.establish(watcher, r, other, r);
+        let batch = set.task_exited(other, r, Time::ZERO, DownReason::Normal, traps);
+        let leftover = set.dispatch(batch);
+        assert!(leftover.is_empty());
+        assert_eq!(inbox.lock().len(), 2);
+        assert_eq!(inbox.lock()[1].reason, DownReason::Normal);
+
+        // Turning trapping off restores propagation.
+        let last = test_task_id(5, 0);
+        set.establish(owner, r, last, r);
+        let crash = DownReason::Error("x".into());
+        let actions = set.dispatch(set.resolve_exits(last, Time::ZERO, &crash));
+        assert!(matches!(actions[0], LinkExitAction::CancelPeer { .. }));
+    }
+
+    #[test]
+    fn trap_exits_respects_explicit_ignore_and_missing_mailbox() {
+        let r = test_region_id(0, 0);
+        let (owner, quiet, loud) = (test_task_id(1, 0), test_task_id(2, 0), test_task_id(3, 0));
+        let mut set = LinkSet::new();
+        let traps = |task| task == owner;
+        set.establish_with_policy(
+            owner,
+            r,
+            ExitPolicy::Ignore,
+            quiet,
+            r,
+            ExitPolicy::Propagate,
+        );
+        set.establish(owner, r, loud, r);
+
+        let crash = DownReason::Error("x".into());
+        let batch = set.task_exited(quiet, r, Time::ZERO, crash.clone(), traps);
+        let ignored = set.dispatch(batch);
+        assert!(matches!(ignored[0], LinkExitAction::Ignored { .. }));
+
+        // Without a mailbox the trapped exit is handed back to the caller.
+        let batch = set.task_exited(loud, r, Time::ZERO, crash, traps);
+        let undelivered = set.dispatch(batch);
+        assert!(matches!(
+            &undelivered[0],
+            LinkExitAction::DeliverExit { to, signal } if *to == owner && signal.from == loud
+        ));
+    }
+
+    #[test]
+    fn kill_is_never_trappable() {
+        let r = test_region_id(0, 0);
+        let (owner, victim, bystander) =
+            (test_task_id(1, 0), test_task_id(2, 0), test_task_id(3, 0));
+        let mut set = LinkSet::new();
+        let (mailbox, inbox) = collecting_mailbox();
+        set.set_exit_mailbox(owner, mailbox);
+        set.establish(owner, r, victim, r);
+        set.establish_with_policy(
+            bystander,
+            r,
+            ExitPolicy::Trap,
+            victim,
+            r,
+            ExitPolicy::Propagate,
+        );
+
+        let reason = DownReason::Cancelled(CancelReason::user("kill"));
+        let traps = |task| task == owner;
+        assert!(set.mark_killed(victim));
+        let batch = set.task_exited(victim, r, Time::from_nanos(3), reason, traps);
+        let actions = set.dispatch(batch);
+        let cancelled: Vec<TaskId> = actions
+            .iter()
+            .filter_map(|action| match action {
+                LinkExitAction::CancelPeer { to, .. } => Some(*to),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(cancelled, vec![owner, bystander]);
+        assert!(inbox.lock().is_empty());
+        assert!(set.mark_killed(victim), "the kill mark is consumed on exit");
+    }
+
+    #[test]
+    fn link_to_exited_task_signals_immediately() {
+        let r = test_region_id(0, 0);
+        let (dead, trapper, plain) = (test_task_id(1, 0), test_task_id(2, 0), test_task_id(3, 0));
+        let mut set = LinkSet::new();
+        let crash = DownReason::Panicked(PanicPayload::new("gone"));
+        assert!(
+            set.task_exited(dead, r, Time::from_nanos(5), crash, |_| false)
+                .into_sorted()
+                .is_empty()
+        );
+        assert!(set.has_exited(dead));
+        assert_eq!(set.exited_region(dead), Some(r));
+
+        let (mailbox, inbox) = collecting_mailbox();
+        set.set_exit_mailbox(trapper, mailbox);
+        let trapped_ref = set.establish(trapper, r, dead, r);
+        let cancelled_ref = set.establish(dead, r, plain, r);
+
+        // No live link to a dead task is ever created.
+        assert!(set.is_empty());
+        let pending = set.take_pending_exits(|task| task == trapper);
+        let actions = set.dispatch(pending);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            &actions[0],
+            LinkExitAction::CancelPeer { to, link_ref, .. }
+                if *to == plain && *link_ref == cancelled_ref
+        ));
+        let inbox = inbox.lock();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].from, dead);
+        assert_eq!(inbox[0].link_ref, trapped_ref);
+        assert!(set.take_pending_exits(|_| true).into_sorted().is_empty());
+
+        // Region cleanup forgets the tombstone.
+        set.cleanup_region(r);
+        assert!(!set.has_exited(dead));
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Inbox {
+        Data(u32),
+        Exit(TaskId),
+    }
+
+    impl From<ExitSignal> for Inbox {
+        fn from(signal: ExitSignal) -> Self {
+            Self::Exit(signal.from)
+        }
+    }
+
+    #[test]
+    fn exit_signal_follows_dead_tasks_last_message() {
+        let r = test_region_id(0, 0);
+        let (owner, worker) = (test_task_id(1, 0), test_task_id(2, 0));
+        let (tx, mut rx) = crate::channel::mpsc::channel::<Inbox>(8);
+        let mut set = LinkSet::new();
+        set.set_exit_mailbox(owner, ExitMailbox::from_sender(tx.clone()));
+        set.establish(owner, r, worker, r);
+
+        // The worker's last messages are sent before it terminates.
+        tx.try_send(Inbox::Data(1)).unwrap();
+        tx.try_send(Inbox::Data(2)).unwrap();
+        let reason = DownReason::Error("done".into());
+        let batch = set.task_exited(worker, r, Time::ZERO, reason, |task| task == owner);
+        let (deliveries, rest) = set.route(batch);
+        assert!(rest.is_empty());
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries.dispatch(), 0);
+
+        let received: Vec<Inbox> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(
+            received,
+            vec![Inbox::Data(1), Inbox::Data(2), Inbox::Exit(worker)]
+        );
+    }
+
+    #[test]
+    fn link_and_monitor_each_notify_exactly_once() {
+        use crate::monitor::MonitorSet;
+
+        let r = test_region_id(0, 0);
+        let (owner, worker) = (test_task_id(1, 0), test_task_id(2, 0));
+        let mut links = LinkSet::new();
+        let mut monitors = MonitorSet::new();
+        let (mailbox, inbox) = collecting_mailbox();
+        links.set_exit_mailbox(owner, mailbox);
+        links.establish(owner, r, worker, r);
+        let mref = monitors.establish(owner, r, worker);
+
+        let reason = DownReason::Error("crash".into());
+        let downs = monitors.watchers_of(worker);
+        let traps = |task| task == owner;
+        let batch = links.task_exited(worker, r, Time::ZERO, reason.clone(), traps);
+        assert!(links.dispatch(batch).is_empty());
+        assert_eq!(monitors.remove_monitored(worker), vec![mref]);
+        assert_eq!(downs, vec![(mref, owner)]);
+        assert_eq!(inbox.lock().len(), 1);
+
+        // A second report produces nothing on either side.
+        assert!(
+            links
+                .resolve_exits(worker, Time::ZERO, &reason)
+                .into_sorted()
+                .is_empty()
+        );
+        assert!(monitors.watchers_of(worker).is_empty());
+
+        // Unlinking does not disturb an independent monitor.
+        let other = test_task_id(3, 0);
+        let lref = links.establish(owner, r, other, r);
+        let other_mref = monitors.establish(owner, r, other);
+        assert!(links.unlink(lref));
+        assert_eq!(monitors.watchers_of(other), vec![(other_mref, owner)]);
+    }
 }
@@ -43,7 +43,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::cancel::CancelReason;
 use crate::types::outcome::PanicPayload;
-use crate::types::{Outcome, RegionId, TaskId, Time};
+use crate::runtime::RuntimeState;
+use crate::types::{Outcome, RegionId, StaleId, TaskId, Time};
 
 // ============================================================================
 // MonitorRef
@@ -282,6 +283,30 @@ impl MonitorSet {
         monitor_ref
     }
 
+    /// Establishes a monitor after checking that every id still names a live
+    /// entity in `state`.
+    ///
+    /// Use this when the ids come from outside the runtime (an operator
+    /// console, a remote peer): a stale `monitored` id would otherwise attach
+    /// the monitor to whichever task now occupies the reused slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] for the first id that no longer names a live task
+    /// or region; no monitor is created.
+    pub fn establish_checked(
+        &mut self,
+        state: &RuntimeState,
+        watcher: TaskId,
+        watcher_region: RegionId,
+        monitored: TaskId,
+    ) -> Result<MonitorRef, StaleId> {
+        state.checked_task(watcher)?;
+        state.checked_region(watcher_region)?;
+        state.checked_task(monitored)?;
+        Ok(self.establish(watcher, watcher_region, monitored))
+    }
+
     /// Removes a specific monitor. Returns `true` if it existed.
     pub fn demonitor(&mut self, monitor_ref: MonitorRef) -> bool {
         let Some(record) = self.by_ref.remove(&monitor_ref) else {
@@ -482,6 +507,38 @@ mod tests {
         RegionId::new_for_test(index, generation)
     }
 
+    fn live_task(state: &mut RuntimeState, region: RegionId) -> TaskId {
+        let idx = state.insert_task(crate::record::TaskRecord::new(
+            TaskId::testing_default(),
+            region,
+            crate::types::Budget::INFINITE,
+        ));
+        TaskId::from_arena(idx)
+    }
+
+    #[test]
+    fn establish_checked_rejects_stale_monitored_id() {
+        let mut state = RuntimeState::new();
+        let region = state.create_root_region(crate::types::Budget::INFINITE);
+        let watcher = live_task(&mut state, region);
+        let gone = live_task(&mut state, region);
+        state.remove_task(gone).expect("remove task");
+        let reused = live_task(&mut state, region);
+        assert_eq!(reused.arena_index().index(), gone.arena_index().index());
+
+        let mut set = MonitorSet::new();
+        let err = set
+            .establish_checked(&state, watcher, region, gone)
+            .expect_err("stale monitored id");
+        assert_eq!(err.current, Some(reused.arena_index().generation()));
+        assert!(set.is_empty(), "no monitor on the new occupant");
+
+        let mref = set
+            .establish_checked(&state, watcher, region, reused)
+            .expect("live ids");
+        assert_eq!(set.monitored_of(mref), Some(reused));
+    }
+
     // ── MonitorRef ──────────────────────────────────────────────────────
 
     #[test]
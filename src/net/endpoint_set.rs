@@ -0,0 +1,1013 @@
+//! Client-side endpoint sets and per-request balancing.
+//!
+//! An [`EndpointSet`] holds the backend addresses a client balances across.
+//! It is fed by static configuration ([`EndpointSet::new`]), by a DNS name
+//! re-resolved on its record TTL ([`DnsEndpointSource`]), or by any other
+//! discovery feed through [`EndpointSet::update`].
+//!
+//! Every request takes an [`EndpointLease`] from [`EndpointSet::pick`]. The
+//! lease counts toward the endpoint's in-flight load until it is resolved with
+//! [`EndpointLease::succeed`] or [`EndpointLease::fail`]; dropping it records
+//! no outcome. Outcomes drive health tracking:
+//!
+//! - `consecutive_failures` failures in a row eject the endpoint for a
+//!   cooldown that doubles per repeated ejection, capped at `max_cooldown` and
+//!   jittered by the set's seeded RNG.
+//! - Once the cooldown elapses the endpoint admits a single probe request. A
+//!   successful probe restores it; a failed probe ejects it again.
+//! - When every endpoint is ejected, picks fail open across the whole set.
+//!
+//! Membership changes never drop in-flight requests: a removed endpoint stops
+//! receiving picks, its outstanding leases resolve normally, and its state is
+//! discarded once they drain.
+//!
+//! Policy randomness (subset choice, two-choice sampling, cooldown jitter)
+//! comes from [`EndpointSetConfig::seed`] and time from the configured time
+//! getter, so a set driven by the lab clock replays identically.
+//!
+//! The HTTP client balances per request through
+//! [`HttpClientBuilder::endpoint_set`](crate::http::h1::HttpClientBuilder::endpoint_set),
+//! keeping one connection sub-pool per endpoint. gRPC channels pick per new
+//! subchannel through [`Channel::pick_subchannel`](crate::grpc::Channel::pick_subchannel).
+
+use crate::net::dns::{DnsError, LookupIp, Resolver};
+use crate::types::Time;
+use crate::util::DetRng;
+use parking_lot::Mutex;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+type EndpointTimeGetter = Arc<dyn Fn() -> Time + Send + Sync + 'static>;
+
+fn wall_clock_now() -> Time {
+    crate::time::wall_now()
+}
+
+fn duration_to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Per-request endpoint selection policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalancePolicy {
+    /// Rotate through available endpoints in address order.
+    #[default]
+    RoundRobin,
+    /// Pick the available endpoint with the fewest in-flight requests,
+    /// rotating among ties.
+    LeastOutstanding,
+    /// Restrict this client to a deterministic subset of the endpoints, then
+    /// pick the less loaded of two random subset members.
+    ///
+    /// Subset membership is ranked by a hash of the set seed and each address,
+    /// so adding or removing one endpoint moves at most one subset slot.
+    SubsetPowerOfTwo {
+        /// Endpoints in this client's subset.
+        subset_size: usize,
+    },
+}
+
+/// Outlier ejection settings for an [`EndpointSet`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EjectionConfig {
+    /// Failures in a row that eject an endpoint (`0` disables ejection).
+    pub consecutive_failures: u32,
+    /// Cooldown after the first ejection.
+    pub base_cooldown: Duration,
+    /// Upper bound for the cooldown, which doubles per repeated ejection.
+    pub max_cooldown: Duration,
+    /// Fractional jitter applied to each cooldown, clamped to `[0, 1]`.
+    pub jitter: f64,
+}
+
+impl Default for EjectionConfig {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 5,
+            base_cooldown: Duration::from_secs(5),
+            max_cooldown: Duration::from_secs(120),
+            jitter: 0.2,
+        }
+    }
+}
+
+/// Configuration for an [`EndpointSet`].
+#[derive(Clone)]
+pub struct EndpointSetConfig {
+    /// Endpoint selection policy.
+    pub policy: BalancePolicy,
+    /// Health tracking and ejection.
+    pub ejection: EjectionConfig,
+    /// Distinct endpoints a single request may try. `1` disables retrying on
+    /// a different endpoint.
+    pub max_attempts: u32,
+    /// Seed for the policy RNG.
+    pub seed: u64,
+    time_getter: EndpointTimeGetter,
+}
+
+impl Default for EndpointSetConfig {
+    fn default() -> Self {
+        Self {
+            policy: BalancePolicy::default(),
+            ejection: EjectionConfig::default(),
+            max_attempts: 2,
+            seed: 0,
+            time_getter: Arc::new(wall_clock_now),
+        }
+    }
+}
+
+impl EndpointSetConfig {
+    /// Creates a configuration with `policy` and default health settings.
+    #[must_use]
+    pub fn new(policy: BalancePolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the policy RNG seed.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the ejection settings.
+    #[must_use]
+    pub const fn with_ejection(mut self, ejection: EjectionConfig) -> Self {
+        self.ejection = ejection;
+        self
+    }
+
+    /// Sets how many distinct endpoints a single request may try.
+    #[must_use]
+    pub const fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set a deterministic time source.
+    #[must_use]
+    pub fn with_time_getter<F>(mut self, time_getter: F) -> Self
+    where
+        F: Fn() -> Time + Send + Sync + 'static,
+    {
+        self.time_getter = Arc::new(time_getter);
+        self
+    }
+}
+
+impl fmt::Debug for EndpointSetConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EndpointSetConfig")
+            .field("policy", &self.policy)
+            .field("ejection", &self.ejection)
+            .field("max_attempts", &self.max_attempts)
+            .field("seed", &self.seed)
+            .field("time_getter", &"<fn>")
+            .finish()
+    }
+}
+
+/// Point-in-time counters for one endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointMetrics {
+    /// Endpoint address.
+    pub addr: SocketAddr,
+    /// Leases currently outstanding.
+    pub in_flight: usize,
+    /// Leases handed out in total.
+    pub requests: u64,
+    /// Leases resolved as failures in total.
+    pub failures: u64,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// End of the current ejection, if the endpoint is ejected.
+    pub ejected_until: Option<Time>,
+    /// Moving average of successful request latency.
+    pub latency_ewma: Option<Duration>,
+    /// The endpoint left the set and is draining its in-flight leases.
+    pub draining: bool,
+}
+
+#[derive(Debug)]
+struct EndpointState {
+    addr: SocketAddr,
+    in_flight: usize,
+    requests: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    ejections: u32,
+    ejected_until: Option<Time>,
+    probe_in_flight: bool,
+    latency_ewma_nanos: Option<u64>,
+    draining: bool,
+}
+
+impl EndpointState {
+    const fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            in_flight: 0,
+            requests: 0,
+            failures: 0,
+            consecutive_failures: 0,
+            ejections: 0,
+            ejected_until: None,
+            probe_in_flight: false,
+            latency_ewma_nanos: None,
+            draining: false,
+        }
+    }
+
+    fn available(&self, now: Time) -> bool {
+        self.ejected_until
+            .is_none_or(|until| now >= until && !self.probe_in_flight)
+    }
+
+    fn metrics(&self) -> EndpointMetrics {
+        EndpointMetrics {
+            addr: self.addr,
+            in_flight: self.in_flight,
+            requests: self.requests,
+            failures: self.failures,
+            consecutive_failures: self.consecutive_failures,
+            ejected_until: self.ejected_until,
+            latency_ewma: self.latency_ewma_nanos.map(Duration::from_nanos),
+            draining: self.draining,
+        }
+    }
+
+    fn record_latency(&mut self, sample_nanos: u64) {
+        let previous = self.latency_ewma_nanos.unwrap_or(sample_nanos);
+        self.latency_ewma_nanos = Some(previous - previous / 8 + sample_nanos / 8);
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+    fn eject(&mut self, config: &EjectionConfig, rng: &mut DetRng, now: Time) {
+        self.ejections = self.ejections.saturating_add(1);
+        self.consecutive_failures = 0;
+        let doubling = 1_u64 << (self.ejections - 1).min(32);
+        let cooldown = duration_to_nanos(config.base_cooldown)
+            .saturating_mul(doubling)
+            .min(duration_to_nanos(config.max_cooldown));
+        let jitter = config.jitter.clamp(0.0, 1.0);
+        let unit = (rng.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        let factor = 2.0f64.mul_add(unit, -1.0).mul_add(jitter, 1.0);
+        let cooldown = (cooldown as f64 * factor) as u64;
+        self.ejected_until = Some(now.saturating_add_nanos(cooldown));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LeaseOutcome {
+    Success,
+    Failure,
+    Abandoned,
+}
+
+#[derive(Debug)]
+struct SetState {
+    /// Sorted by address so rotation and ties are deterministic.
+    endpoints: Vec<EndpointState>,
+    subset: Vec<SocketAddr>,
+    cursor: usize,
+    rng: DetRng,
+}
+
+impl SetState {
+    fn position(&self, addr: SocketAddr) -> Result<usize, usize> {
+        self.endpoints
+            .binary_search_by(|endpoint| endpoint.addr.cmp(&addr))
+    }
+
+    /// First candidate at or after the rotation cursor. `candidates` is sorted.
+    fn next_in_rotation(&mut self, candidates: &[usize]) -> usize {
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|&index| index >= self.cursor)
+            .unwrap_or(candidates[0]);
+        self.cursor = index + 1;
+        index
+    }
+
+    fn two_choices(&mut self, pool: &[usize]) -> usize {
+        if pool.len() == 1 {
+            return pool[0];
+        }
+        let first = self.rng.next_usize(pool.len());
+        let mut second = self.rng.next_usize(pool.len() - 1);
+        if second >= first {
+            second += 1;
+        }
+        let (first, second) = (pool[first], pool[second]);
+        if self.endpoints[second].in_flight < self.endpoints[first].in_flight {
+            second
+        } else {
+            first
+        }
+    }
+
+    fn recompute_subset(&mut self, policy: BalancePolicy, seed: u64) {
+        self.subset.clear();
+        let BalancePolicy::SubsetPowerOfTwo { subset_size } = policy else {
+            return;
+        };
+        let mut ranked: Vec<(u64, SocketAddr)> = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.draining)
+            .map(|endpoint| (subset_rank(seed, endpoint.addr), endpoint.addr))
+            .collect();
+        ranked.sort_unstable();
+        self.subset = ranked
+            .into_iter()
+            .take(subset_size.max(1))
+            .map(|(_, addr)| addr)
+            .collect();
+    }
+}
+
+fn subset_rank(seed: u64, addr: SocketAddr) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    addr.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct SetInner {
+    config: EndpointSetConfig,
+    state: Mutex<SetState>,
+}
+
+/// A balanced set of backend endpoints shared by every clone.
+#[derive(Clone)]
+pub struct EndpointSet {
+    inner: Arc<SetInner>,
+}
+
+impl fmt::Debug for EndpointSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EndpointSet")
+            .field("config", &self.inner.config)
+            .field("endpoints", &self.inner.state.lock().endpoints.len())
+            .finish()
+    }
+}
+
+impl EndpointSet {
+    /// Creates a set over a static list of endpoints.
+    #[must_use]
+    pub fn new(config: EndpointSetConfig, endpoints: impl IntoIterator<Item = SocketAddr>) -> Self {
+        let state = SetState {
+            endpoints: Vec::new(),
+            subset: Vec::new(),
+            cursor: 0,
+            rng: DetRng::new(config.seed),
+        };
+        let set = Self {
+            inner: Arc::new(SetInner {
+                config,
+                state: Mutex::new(state),
+            }),
+        };
+        set.update(endpoints);
+        set
+    }
+
+    /// Returns the set configuration.
+    #[must_use]
+    pub fn config(&self) -> &EndpointSetConfig {
+        &self.inner.config
+    }
+
+    /// Distinct endpoints a single request may try (at least one).
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.inner.config.max_attempts.max(1)
+    }
+
+    /// Number of endpoints accepting new requests.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner
+            .state
+            .lock()
+            .endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.draining)
+            .count()
+    }
+
+    /// Returns true if no endpoint accepts new requests.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Endpoints accepting new requests, in address order.
+    #[must_use]
+    pub fn addrs(&self) -> Vec<SocketAddr> {
+        self.inner
+            .state
+            .lock()
+            .endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.draining)
+            .map(|endpoint| endpoint.addr)
+            .collect()
+    }
+
+    /// Per-endpoint counters, including draining endpoints.
+    #[must_use]
+    pub fn metrics(&self) -> Vec<EndpointMetrics> {
+        self.inner
+            .state
+            .lock()
+            .endpoints
+            .iter()
+            .map(EndpointState::metrics)
+            .collect()
+    }
+
+    /// Replaces the set membership.
+    ///
+    /// New addresses start healthy. Addresses that are no longer listed stop
+    /// receiving picks; their in-flight leases stay valid and their state is
+    /// dropped once the last one resolves.
+    pub fn update(&self, endpoints: impl IntoIterator<Item = SocketAddr>) {
+        let mut next: Vec<SocketAddr> = endpoints.into_iter().collect();
+        next.sort_unstable();
+        next.dedup();
+
+        let mut state = self.inner.state.lock();
+        state.endpoints.retain_mut(|endpoint| {
+            endpoint.draining = next.binary_search(&endpoint.addr).is_err();
+            !endpoint.draining || endpoint.in_flight > 0
+        });
+        for addr in next {
+            if let Err(index) = state.position(addr) {
+                state.endpoints.insert(index, EndpointState::new(addr));
+            }
+        }
+        state.recompute_subset(self.inner.config.policy, self.inner.config.seed);
+    }
+
+    /// Picks an endpoint for one request, skipping `exclude`.
+    ///
+    /// Returns `None` only when no endpoint outside `exclude` is in the set.
+    #[must_use]
+    pub fn pick(&self, exclude: &[SocketAddr]) -> Option<EndpointLease> {
+        let now = self.now();
+        let mut guard = self.inner.state.lock();
+        let state = &mut *guard;
+        let eligible: Vec<usize> = state
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, endpoint)| !endpoint.draining && !exclude.contains(&endpoint.addr))
+            .map(|(index, _)| index)
+            .collect();
+        let mut candidates: Vec<usize> = eligible
+            .iter()
+            .copied()
+            .filter(|&index| state.endpoints[index].available(now))
+            .collect();
+        if candidates.is_empty() {
+            // Every eligible endpoint is ejected; fail open rather than
+            // failing the request outright.
+            candidates = eligible;
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let index = match self.inner.config.policy {
+            BalancePolicy::RoundRobin => state.next_in_rotation(&candidates),
+            BalancePolicy::LeastOutstanding => {
+                let least = candidates
+                    .iter()
+                    .map(|&index| state.endpoints[index].in_flight)
+                    .min()
+                    .unwrap_or(0);
+                candidates.retain(|&index| state.endpoints[index].in_flight == least);
+                state.next_in_rotation(&candidates)
+            }
+            BalancePolicy::SubsetPowerOfTwo { .. } => {
+                let in_subset: Vec<usize> = candidates
+                    .iter()
+                    .copied()
+                    .filter(|&index| state.subset.contains(&state.endpoints[index].addr))
+                    .collect();
+                if in_subset.is_empty() {
+                    state.two_choices(&candidates)
+                } else {
+                    state.two_choices(&in_subset)
+                }
+            }
+        };
+
+        let endpoint = &mut state.endpoints[index];
+        let probe = endpoint.ejected_until.is_some_and(|until| now >= until);
+        endpoint.in_flight += 1;
+        endpoint.requests += 1;
+        endpoint.probe_in_flight |= probe;
+        Some(EndpointLease {
+            set: self.clone(),
+            addr: endpoint.addr,
+            started: now,
+            probe,
+            resolved: false,
+        })
+    }
+
+    fn now(&self) -> Time {
+        (self.inner.config.time_getter)()
+    }
+
+    fn finish(&self, addr: SocketAddr, started: Time, probe: bool, outcome: LeaseOutcome) {
+        let now = self.now();
+        let ejection = &self.inner.config.ejection;
+        let mut guard = self.inner.state.lock();
+        let state = &mut *guard;
+        let Ok(index) = state.position(addr) else {
+            return;
+        };
+        let endpoint = &mut state.endpoints[index];
+        endpoint.in_flight = endpoint.in_flight.saturating_sub(1);
+        if probe {
+            endpoint.probe_in_flight = false;
+        }
+        match outcome {
+            LeaseOutcome::Success => {
+                endpoint.consecutive_failures = 0;
+                endpoint.ejections = 0;
+                endpoint.ejected_until = None;
+                endpoint.record_latency(now.duration_since(started));
+            }
+            LeaseOutcome::Failure => {
+                endpoint.failures += 1;
+                endpoint.consecutive_failures = endpoint.consecutive_failures.saturating_add(1);
+                if ejection.consecutive_failures > 0
+                    && (probe || endpoint.consecutive_failures >= ejection.consecutive_failures)
+                {
+                    endpoint.eject(ejection, &mut state.rng, now);
+                }
+            }
+            LeaseOutcome::Abandoned => {}
+        }
+        if endpoint.draining && endpoint.in_flight == 0 {
+            state.endpoints.remove(index);
+        }
+    }
+}
+
+/// One request's claim on an endpoint of an [`EndpointSet`].
+#[must_use = "dropping a lease records no outcome for the endpoint"]
+pub struct EndpointLease {
+    set: EndpointSet,
+    addr: SocketAddr,
+    started: Time,
+    probe: bool,
+    resolved: bool,
+}
+
+impl EndpointLease {
+    /// Address to send the request to.
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns true if this request is the re-probe of an ejected endpoint.
+    #[must_use]
+    pub const fn is_probe(&self) -> bool {
+        self.probe
+    }
+
+    /// Records a successful request and its latency.
+    pub fn succeed(mut self) {
+        self.resolve(LeaseOutcome::Success);
+    }
+
+    /// Records a failed request toward the endpoint's ejection threshold.
+    pub fn fail(mut self) {
+        self.resolve(LeaseOutcome::Failure);
+    }
+
+    fn resolve(&mut self, outcome: LeaseOutcome) {
+        if !self.resolved {
+            self.resolved = true;
+            self.set.finish(self.addr, self.started, self.probe, outcome);
+        }
+    }
+}
+
+impl Drop for EndpointLease {
+    fn drop(&mut self) {
+        self.resolve(LeaseOutcome::Abandoned);
+    }
+}
+
+impl fmt::Debug for EndpointLease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EndpointLease")
+            .field("addr", &self.addr)
+            .field("started", &self.started)
+            .field("probe", &self.probe)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Keeps an [`EndpointSet`] in sync with a DNS name, re-resolving once the
+/// previous answer's TTL has expired.
+#[derive(Debug)]
+pub struct DnsEndpointSource {
+    resolver: Resolver,
+    host: String,
+    port: u16,
+    min_ttl: Duration,
+    refresh_at: Option<Time>,
+}
+
+impl DnsEndpointSource {
+    /// Creates a source resolving `host` and dialing every address on `port`.
+    #[must_use]
+    pub fn new(resolver: Resolver, host: impl Into<String>, port: u16) -> Self {
+        Self {
+            resolver,
+            host: host.into(),
+            port,
+            min_ttl: Duration::from_secs(1),
+            refresh_at: None,
+        }
+    }
+
+    /// Sets the floor applied to record TTLs (default 1s), so zero-TTL
+    /// answers do not turn every request into a lookup.
+    #[must_use]
+    pub const fn with_min_ttl(mut self, min_ttl: Duration) -> Self {
+        self.min_ttl = min_ttl;
+        self
+    }
+
+    /// When the current answer expires, if one has been applied.
+    #[must_use]
+    pub const fn refresh_at(&self) -> Option<Time> {
+        self.refresh_at
+    }
+
+    /// Returns true if the set has never been resolved or its TTL expired.
+    #[must_use]
+    pub fn needs_refresh(&self, now: Time) -> bool {
+        self.refresh_at.is_none_or(|at| now >= at)
+    }
+
+    /// Resolves the name and applies the answer to `set`.
+    ///
+    /// Returns the number of endpoints accepting requests afterwards.
+    pub async fn refresh(&mut self, set: &EndpointSet) -> Result<usize, DnsError> {
+        let lookup = self.resolver.lookup_ip(&self.host).await?;
+        Ok(self.apply(set, &lookup, set.now()))
+    }
+
+    /// Re-resolves only if the previous answer's TTL expired.
+    ///
+    /// Returns whether a lookup ran.
+    pub async fn refresh_if_stale(&mut self, set: &EndpointSet) -> Result<bool, DnsError> {
+        if !self.needs_refresh(set.now()) {
+            return Ok(false);
+        }
+        self.refresh(set).await?;
+        Ok(true)
+    }
+
+    /// Applies an already-resolved answer to `set` at `now`.
+    ///
+    /// An empty answer keeps the previous endpoints. Returns the number of
+    /// endpoints accepting requests afterwards.
+    pub fn apply(&mut self, set: &EndpointSet, lookup: &LookupIp, now: Time) -> usize {
+        if !lookup.addresses().is_empty() {
+            let endpoints = lookup
+                .addresses()
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, self.port));
+            set.update(endpoints);
+        }
+        let ttl = lookup.ttl().max(self.min_ttl);
+        self.refresh_at = Some(now.saturating_add_nanos(duration_to_nanos(ttl)));
+        set.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn addr(last: u8) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)), 8080)
+    }
+
+    fn addrs(count: u8) -> Vec<SocketAddr> {
+        (1..=count).map(addr).collect()
+    }
+
+    fn virtual_clock() -> (Arc<AtomicU64>, impl Fn() -> Time + Send + Sync + 'static) {
+        let nanos = Arc::new(AtomicU64::new(0));
+        let reader = Arc::clone(&nanos);
+        (nanos, move || Time::from_nanos(reader.load(Ordering::SeqCst)))
+    }
+
+    /// Takes (and abandons) a few picks, returning true if none hit `avoided`.
+    fn picks_avoid(set: &EndpointSet, avoided: SocketAddr) -> bool {
+        let mut picked = Vec::new();
+        for _ in 0..4 {
+            picked.push(set.pick(&[]).expect("pick").addr());
+        }
+        !picked.contains(&avoided)
+    }
+
+    fn pick_counts(set: &EndpointSet, picks: usize) -> BTreeMap<SocketAddr, usize> {
+        let mut counts = BTreeMap::new();
+        for _ in 0..picks {
+            let lease = set.pick(&[]).expect("pick");
+            *counts.entry(lease.addr()).or_insert(0) += 1;
+            lease.succeed();
+        }
+        counts
+    }
+
+    #[test]
+    fn round_robin_spreads_evenly() {
+        init_test("round_robin_spreads_evenly");
+        let set = EndpointSet::new(EndpointSetConfig::default(), addrs(4));
+        let counts = pick_counts(&set, 400);
+        let even = counts.len() == 4 && counts.values().all(|&count| count == 100);
+        crate::assert_with_log!(even, "round robin is exact", "4 x 100", counts);
+        crate::test_complete!("round_robin_spreads_evenly");
+    }
+
+    #[test]
+    fn subset_power_of_two_stays_within_subset() {
+        init_test("subset_power_of_two_stays_within_subset");
+        let policy = BalancePolicy::SubsetPowerOfTwo { subset_size: 4 };
+        let set = EndpointSet::new(EndpointSetConfig::new(policy).with_seed(7), addrs(12));
+        let counts = pick_counts(&set, 800);
+        crate::assert_with_log!(counts.len() == 4, "subset size", 4, counts.len());
+        let balanced = counts.values().all(|&count| (150..=250).contains(&count));
+        crate::assert_with_log!(balanced, "within 25% of 200", "150..=250", counts);
+
+        let replay = EndpointSet::new(EndpointSetConfig::new(policy).with_seed(7), addrs(12));
+        let replayed = pick_counts(&replay, 800);
+        crate::assert_with_log!(replayed == counts, "seeded replay", counts, replayed);
+        crate::test_complete!("subset_power_of_two_stays_within_subset");
+    }
+
+    #[test]
+    fn ejection_and_reprobe_follow_virtual_time() {
+        init_test("ejection_and_reprobe_follow_virtual_time");
+        let (clock, now) = virtual_clock();
+        let config = EndpointSetConfig::default()
+            .with_ejection(EjectionConfig {
+                consecutive_failures: 3,
+                base_cooldown: Duration::from_secs(10),
+                max_cooldown: Duration::from_secs(60),
+                jitter: 0.0,
+            })
+            .with_time_getter(now);
+        let set = EndpointSet::new(config, addrs(2));
+        let bad = addr(1);
+
+        for _ in 0..3 {
+            set.pick(&[addr(2)]).expect("pick bad").fail();
+        }
+        let ejected_until = set.metrics()[0].ejected_until;
+        crate::assert_with_log!(
+            ejected_until == Some(Time::from_secs(10)),
+            "ejected for the base cooldown",
+            Some(Time::from_secs(10)),
+            ejected_until
+        );
+
+        clock.store(Time::from_secs(10).as_nanos() - 1, Ordering::SeqCst);
+        let avoided = picks_avoid(&set, bad);
+        crate::assert_with_log!(avoided, "ejected endpoint skipped", true, avoided);
+
+        clock.store(Time::from_secs(10).as_nanos(), Ordering::SeqCst);
+        let mut probe = None;
+        for _ in 0..2 {
+            let lease = set.pick(&[]).expect("pick");
+            if lease.addr() == bad {
+                probe = Some(lease);
+            } else {
+                lease.succeed();
+            }
+        }
+        let probe = probe.expect("cooldown elapsed admits a probe");
+        crate::assert_with_log!(probe.is_probe(), "probe lease", true, probe.is_probe());
+        let single = picks_avoid(&set, bad);
+        crate::assert_with_log!(single, "one probe at a time", true, single);
+
+        probe.fail();
+        let ejected_until = set.metrics()[0].ejected_until;
+        crate::assert_with_log!(
+            ejected_until == Some(Time::from_secs(30)),
+            "failed probe doubles the cooldown",
+            Some(Time::from_secs(30)),
+            ejected_until
+        );
+
+        clock.store(Time::from_secs(30).as_nanos(), Ordering::SeqCst);
+        set.pick(&[addr(2)]).expect("second probe").succeed();
+        let restored = set.metrics()[0];
+        crate::assert_with_log!(
+            restored.ejected_until.is_none() && restored.consecutive_failures == 0,
+            "successful probe restores",
+            "healthy",
+            restored
+        );
+        crate::test_complete!("ejection_and_reprobe_follow_virtual_time");
+    }
+
+    #[test]
+    fn ejection_jitter_is_bounded_and_seeded() {
+        init_test("ejection_jitter_is_bounded_and_seeded");
+        let ejected_until = |seed: u64| {
+            let config = EndpointSetConfig::default()
+                .with_seed(seed)
+                .with_ejection(EjectionConfig {
+                    consecutive_failures: 1,
+                    base_cooldown: Duration::from_secs(10),
+                    max_cooldown: Duration::from_secs(60),
+                    jitter: 0.5,
+                })
+                .with_time_getter(|| Time::ZERO);
+            let set = EndpointSet::new(config, addrs(1));
+            set.pick(&[]).expect("pick").fail();
+            set.metrics()[0].ejected_until.expect("ejected")
+        };
+        let first = ejected_until(11);
+        let bounded = (Time::from_secs(5)..=Time::from_secs(15)).contains(&first);
+        crate::assert_with_log!(bounded, "cooldown within jitter", "5s..=15s", first);
+        let replay = ejected_until(11);
+        crate::assert_with_log!(replay == first, "seeded jitter", first, replay);
+        crate::test_complete!("ejection_jitter_is_bounded_and_seeded");
+    }
+
+    #[test]
+    fn excluded_endpoints_are_not_picked() {
+        init_test("excluded_endpoints_are_not_picked");
+        let set = EndpointSet::new(EndpointSetConfig::default(), addrs(3));
+        let first = set.pick(&[]).expect("first attempt");
+        let retry = set.pick(&[first.addr()]).expect("retry attempt");
+        let moved = retry.addr() != first.addr();
+        crate::assert_with_log!(moved, "retry uses a different endpoint", true, moved);
+        let exhausted = set.pick(&addrs(3));
+        crate::assert_with_log!(exhausted.is_none(), "all excluded", "None", exhausted);
+        crate::test_complete!("excluded_endpoints_are_not_picked");
+    }
+
+    #[test]
+    fn membership_change_keeps_in_flight_leases() {
+        init_test("membership_change_keeps_in_flight_leases");
+        let set = EndpointSet::new(EndpointSetConfig::default(), addrs(2));
+        let in_flight = set.pick(&[]).expect("pick");
+        let removed = in_flight.addr();
+        let kept = if removed == addr(1) { addr(2) } else { addr(1) };
+
+        set.update([kept, addr(3)]);
+        crate::assert_with_log!(
+            set.addrs() == vec![kept, addr(3)],
+            "active endpoints",
+            vec![kept, addr(3)],
+            set.addrs()
+        );
+        let draining = set
+            .metrics()
+            .iter()
+            .any(|m| m.addr == removed && m.draining);
+        crate::assert_with_log!(draining, "removed endpoint drains", true, draining);
+        let avoided = picks_avoid(&set, removed);
+        crate::assert_with_log!(avoided, "draining endpoint skipped", true, avoided);
+
+        in_flight.succeed();
+        let gone = set.metrics().iter().all(|m| m.addr != removed);
+        crate::assert_with_log!(gone, "drained endpoint forgotten", true, gone);
+        crate::test_complete!("membership_change_keeps_in_flight_leases");
+    }
+
+    #[test]
+    fn least_outstanding_favors_fast_endpoints() {
+        init_test("least_outstanding_favors_fast_endpoints");
+        let set = EndpointSet::new(
+            EndpointSetConfig::new(BalancePolicy::LeastOutstanding),
+            addrs(2),
+        );
+        let slow = addr(1);
+        let mut outstanding: Vec<(u32, EndpointLease)> = Vec::new();
+        let mut counts = BTreeMap::new();
+        for tick in 0..200_u32 {
+            outstanding.retain(|(due, _)| *due > tick);
+            let least = set
+                .metrics()
+                .iter()
+                .map(|m| m.in_flight)
+                .min()
+                .unwrap_or(0);
+            let lease = set.pick(&[]).expect("pick");
+            let picked_load = set
+                .metrics()
+                .iter()
+                .find(|m| m.addr == lease.addr())
+                .map_or(0, |m| m.in_flight - 1);
+            crate::assert_with_log!(
+                picked_load == least,
+                "picked least loaded",
+                least,
+                picked_load
+            );
+            *counts.entry(lease.addr()).or_insert(0_u32) += 1;
+            let latency = if lease.addr() == slow { 8 } else { 1 };
+            outstanding.push((tick + latency, lease));
+        }
+        let slow_picks = counts.get(&slow).copied().unwrap_or(0);
+        let fast_picks = counts.get(&addr(2)).copied().unwrap_or(0);
+        crate::assert_with_log!(
+            fast_picks > slow_picks * 3,
+            "fast endpoint takes most requests",
+            "fast > 3x slow",
+            (fast_picks, slow_picks)
+        );
+        crate::test_complete!("least_outstanding_favors_fast_endpoints");
+    }
+
+    #[test]
+    fn dns_source_reapplies_on_ttl_without_dropping_in_flight() {
+        init_test("dns_source_reapplies_on_ttl_without_dropping_in_flight");
+        let ip = |last: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, last));
+        let set = EndpointSet::new(EndpointSetConfig::default(), Vec::<SocketAddr>::new());
+        let mut source = DnsEndpointSource::new(Resolver::new(), "backend.test", 8080);
+        crate::assert_with_log!(
+            source.needs_refresh(Time::ZERO),
+            "unresolved",
+            true,
+            source.needs_refresh(Time::ZERO)
+        );
+
+        let first = LookupIp::new(vec![ip(1), ip(2)], Duration::from_secs(30));
+        let active = source.apply(&set, &first, Time::ZERO);
+        crate::assert_with_log!(active == 2, "first answer", 2, active);
+        let fresh = !source.needs_refresh(Time::from_secs(29));
+        crate::assert_with_log!(fresh, "within ttl", true, fresh);
+        let stale = source.needs_refresh(Time::from_secs(30));
+        crate::assert_with_log!(stale, "ttl expired", true, stale);
+
+        let in_flight = set.pick(&[addr(2)]).expect("pick first endpoint");
+        let second = LookupIp::new(vec![ip(2), ip(3)], Duration::from_secs(30));
+        let active = source.apply(&set, &second, Time::from_secs(30));
+        crate::assert_with_log!(active == 2, "second answer", 2, active);
+        crate::assert_with_log!(
+            set.addrs() == vec![addr(2), addr(3)],
+            "re-resolved membership",
+            vec![addr(2), addr(3)],
+            set.addrs()
+        );
+        let draining = set.metrics()[0];
+        crate::assert_with_log!(
+            draining.addr == addr(1) && draining.draining && draining.in_flight == 1,
+            "removed endpoint keeps its in-flight request",
+            addr(1),
+            draining
+        );
+        in_flight.succeed();
+        crate::assert_with_log!(set.metrics().len() == 2, "drained", 2, set.metrics().len());
+
+        let empty = LookupIp::new(Vec::new(), Duration::from_secs(30));
+        let active = source.apply(&set, &empty, Time::from_secs(60));
+        crate::assert_with_log!(active == 2, "empty answer keeps endpoints", 2, active);
+        crate::test_complete!("dns_source_reapplies_on_ttl_without_dropping_in_flight");
+    }
+}
@@ -0,0 +1,443 @@
+//! File descriptor exhaustion handling for listener accept paths.
+//!
+//! When `accept(2)` fails with `EMFILE`/`ENFILE` the pending connection stays
+//! in the kernel backlog and the listening socket stays readable, so a naive
+//! accept loop spins at full CPU while the client hangs until it times out.
+//! Each listener keeps a small guard that turns that failure into bounded,
+//! observable behaviour:
+//!
+//! 1. A rate-limited structured warning event (at most one per
+//!    [`FdExhaustionConfig::event_interval`], with a count of suppressed
+//!    repeats).
+//! 2. Optionally, the listener releases an "emergency" descriptor it reserved
+//!    at startup, accepts the pending connection and closes it at once so the
+//!    client sees a clean FIN/RST instead of hanging, then re-reserves the
+//!    descriptor.
+//! 3. A cooldown measured on the runtime clock (virtual in the lab) during
+//!    which the listener does not call `accept` at all. A single timer wake is
+//!    armed per cooldown, so polls inside the window never spin.
+//!
+//! Without a timer driver the cooldown cannot be scheduled; the listener then
+//! surfaces the typed [`ErrorKind::FdExhausted`](crate::error::ErrorKind)
+//! error (see [`map_fd_exhaustion`](crate::error::map_fd_exhaustion)) and the
+//! caller's own backoff applies.
+//!
+//! The guard also samples process descriptor usage (best effort, via
+//! `/proc/self/fd` on Linux) and emits a warning event when usage crosses
+//! [`FdExhaustionConfig::warning_percent`] of the soft `RLIMIT_NOFILE`.
+
+use crate::cx::Cx;
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::Waker;
+use std::time::Duration;
+
+/// Current runtime time for accept-path bookkeeping: the timer driver's clock
+/// (virtual in the lab) when one is installed, wall time otherwise.
+pub(crate) fn accept_clock_now() -> Time {
+    Cx::current()
+        .and_then(|current| current.timer_driver())
+        .map_or_else(crate::time::wall_now, |driver| driver.now())
+}
+
+/// Tuning for listener behaviour under file descriptor exhaustion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdExhaustionConfig {
+    /// How long the listener stops accepting after an `EMFILE`/`ENFILE`.
+    /// Default: 100ms.
+    pub cooldown: Duration,
+    /// Minimum spacing between structured warning events. Repeats inside the
+    /// window are counted as suppressed. Default: 1s.
+    pub event_interval: Duration,
+    /// Reserve one descriptor at startup and spend it to accept-and-close the
+    /// pending connection when the table is full. Default: true.
+    pub emergency_fd: bool,
+    /// Emit a warning event when open descriptors reach this percentage of
+    /// the soft limit; `None` disables usage sampling. Usage is sampled at
+    /// most once per `event_interval`. Default: 80.
+    pub warning_percent: Option<u8>,
+}
+
+impl Default for FdExhaustionConfig {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::from_millis(100),
+            event_interval: Duration::from_secs(1),
+            emergency_fd: true,
+            warning_percent: Some(80),
+        }
+    }
+}
+
+/// Best-effort process file descriptor usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdUsage {
+    /// Descriptors currently open in this process.
+    pub open: u64,
+    /// Soft `RLIMIT_NOFILE` (the limit `EMFILE` enforces), when known.
+    pub limit: Option<u64>,
+}
+
+impl FdUsage {
+    /// Samples the current process descriptor usage.
+    ///
+    /// Returns `None` on platforms without a descriptor count probe.
+    #[must_use]
+    pub fn sample() -> Option<Self> {
+        use crate::runtime::resource_monitor::platform;
+
+        let open = platform::process_fd_count().ok()?;
+        let limit = platform::fd_rlimit()
+            .ok()
+            .map(|(soft, _)| soft)
+            .filter(|soft| *soft != 0 && *soft != u64::MAX);
+        Some(Self { open, limit })
+    }
+
+    /// Returns usage as a whole percentage of the limit, if one is known.
+    #[must_use]
+    pub fn percent_of_limit(&self) -> Option<u64> {
+        let limit = self.limit.filter(|limit| *limit > 0)?;
+        Some(self.open.saturating_mul(100) / limit)
+    }
+}
+
+/// Point-in-time copy of a listener's descriptor exhaustion counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FdExhaustionSnapshot {
+    /// Accept attempts that failed with `EMFILE`/`ENFILE`.
+    pub exhaustion_events: u64,
+    /// Pending connections accepted and closed with the emergency descriptor.
+    pub emergency_sheds: u64,
+    /// Exhaustion warning events emitted.
+    pub events_emitted: u64,
+    /// Exhaustion warning events suppressed by the rate limit.
+    pub events_suppressed: u64,
+    /// Usage warning events emitted for crossing the warning threshold.
+    pub usage_warnings: u64,
+    /// Whether the emergency descriptor is currently reserved.
+    pub emergency_fd_reserved: bool,
+    /// End of the current accept cooldown, if one was entered.
+    pub cooldown_until: Option<Time>,
+    /// Most recent descriptor usage sample.
+    pub fd_usage: Option<FdUsage>,
+}
+
+/// A reserved descriptor (or simulated slot) held for emergency shedding.
+///
+/// Dropping the reservation releases the descriptor.
+pub(crate) struct EmergencyFd {
+    _reserve: Box<dyn Send>,
+}
+
+impl EmergencyFd {
+    pub(crate) fn new(reserve: impl Send + 'static) -> Self {
+        Self {
+            _reserve: Box::new(reserve),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    cooldown_until: Option<Time>,
+    armed_wake: Option<Time>,
+    last_event_at: Option<Time>,
+    suppressed_since_event: u64,
+    last_usage_sample_at: Option<Time>,
+    fd_usage: Option<FdUsage>,
+}
+
+/// Per-listener exhaustion state: cooldown, event rate limit, emergency
+/// reserve and counters.
+pub(crate) struct AcceptFdGuard {
+    config: FdExhaustionConfig,
+    usage_probe: fn() -> Option<FdUsage>,
+    state: Mutex<GuardState>,
+    emergency: Mutex<Option<EmergencyFd>>,
+    exhaustion_events: AtomicU64,
+    emergency_sheds: AtomicU64,
+    events_emitted: AtomicU64,
+    events_suppressed: AtomicU64,
+    usage_warnings: AtomicU64,
+}
+
+impl fmt::Debug for AcceptFdGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AcceptFdGuard")
+            .field("config", &self.config)
+            .field(
+                "exhaustion_events",
+                &self.exhaustion_events.load(Ordering::Relaxed),
+            )
+            .field(
+                "emergency_sheds",
+                &self.emergency_sheds.load(Ordering::Relaxed),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl AcceptFdGuard {
+    /// Creates a guard, reserving the emergency descriptor from `reserve`
+    /// when the config asks for one.
+    pub(crate) fn new(
+        config: FdExhaustionConfig,
+        reserve: impl FnOnce() -> Option<EmergencyFd>,
+    ) -> Self {
+        Self::with_usage_probe(config, FdUsage::sample, reserve)
+    }
+
+    pub(crate) fn with_usage_probe(
+        config: FdExhaustionConfig,
+        usage_probe: fn() -> Option<FdUsage>,
+        reserve: impl FnOnce() -> Option<EmergencyFd>,
+    ) -> Self {
+        let emergency = if config.emergency_fd { reserve() } else { None };
+        Self {
+            config,
+            usage_probe,
+            state: Mutex::new(GuardState::default()),
+            emergency: Mutex::new(emergency),
+            exhaustion_events: AtomicU64::new(0),
+            emergency_sheds: AtomicU64::new(0),
+            events_emitted: AtomicU64::new(0),
+            events_suppressed: AtomicU64::new(0),
+            usage_warnings: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cooldown deadline if `now` is still inside it.
+    pub(crate) fn cooldown_deadline(&self, now: Time) -> Option<Time> {
+        let mut state = self.state.lock();
+        match state.cooldown_until {
+            Some(until) if now < until => Some(until),
+            Some(_) => {
+                state.cooldown_until = None;
+                state.armed_wake = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Arms a single timer wake for `deadline` on the current runtime's timer
+    /// driver. Returns false when no timer driver is available, in which case
+    /// the cooldown cannot be enforced by the listener.
+    pub(crate) fn arm_cooldown_wake(&self, deadline: Time, waker: Waker) -> bool {
+        let Some(timer) = Cx::current().and_then(|current| current.timer_driver()) else {
+            return false;
+        };
+        let mut state = self.state.lock();
+        if state.armed_wake != Some(deadline) {
+            state.armed_wake = Some(deadline);
+            drop(state);
+            let _ = timer.register(deadline, waker);
+        }
+        true
+    }
+
+    /// Handles an `EMFILE`/`ENFILE` from `accept`.
+    ///
+    /// Emits the rate-limited event, spends the emergency descriptor on
+    /// `shed` (accept the pending connection and close it; returns whether a
+    /// connection was shed), re-reserves with `reacquire`, and enters the
+    /// cooldown. Returns the cooldown deadline.
+    pub(crate) fn on_exhaustion(
+        &self,
+        now: Time,
+        shed: impl FnOnce() -> bool,
+        reacquire: impl FnOnce() -> Option<EmergencyFd>,
+    ) -> Time {
+        self.exhaustion_events.fetch_add(1, Ordering::Relaxed);
+        crate::runtime::metrics::record_fd_exhaustion();
+
+        let released = self.emergency.lock().take();
+        let mut shed_connection = false;
+        if let Some(reserve) = released {
+            drop(reserve);
+            shed_connection = shed();
+            if shed_connection {
+                self.emergency_sheds.fetch_add(1, Ordering::Relaxed);
+                crate::runtime::metrics::record_emergency_fd_shed();
+            }
+            *self.emergency.lock() = reacquire();
+        } else if self.config.emergency_fd {
+            // A previous shed could not re-reserve; try again now that the
+            // cooldown has elapsed.
+            *self.emergency.lock() = reacquire();
+        }
+
+        let deadline = now + self.config.cooldown;
+        let mut state = self.state.lock();
+        state.cooldown_until = Some(deadline);
+        let emit = state.last_event_at.is_none_or(|last| {
+            Duration::from_nanos(now.duration_since(last)) >= self.config.event_interval
+        });
+        if emit {
+            let suppressed = std::mem::take(&mut state.suppressed_since_event);
+            state.last_event_at = Some(now);
+            drop(state);
+            self.events_emitted.fetch_add(1, Ordering::Relaxed);
+            crate::tracing_compat::warn!(
+                event = "fd_exhaustion",
+                shed_connection,
+                suppressed,
+                cooldown_ms = self.config.cooldown.as_millis(),
+                "listener accept hit file descriptor exhaustion; backing off"
+            );
+            let _ = suppressed;
+        } else {
+            state.suppressed_since_event = state.suppressed_since_event.saturating_add(1);
+            drop(state);
+            self.events_suppressed.fetch_add(1, Ordering::Relaxed);
+        }
+        deadline
+    }
+
+    /// Samples descriptor usage (at most once per event interval) and emits a
+    /// warning event when it crosses the configured threshold.
+    pub(crate) fn observe_usage(&self, now: Time) {
+        let Some(threshold) = self.config.warning_percent else {
+            return;
+        };
+        {
+            let mut state = self.state.lock();
+            let due = state.last_usage_sample_at.is_none_or(|last| {
+                Duration::from_nanos(now.duration_since(last)) >= self.config.event_interval
+            });
+            if !due {
+                return;
+            }
+            state.last_usage_sample_at = Some(now);
+        }
+        let usage = (self.usage_probe)();
+        self.state.lock().fd_usage = usage;
+        let Some(percent) = usage.and_then(|usage| usage.percent_of_limit()) else {
+            return;
+        };
+        if percent >= u64::from(threshold) {
+            self.usage_warnings.fetch_add(1, Ordering::Relaxed);
+            crate::tracing_compat::warn!(
+                event = "fd_usage_high",
+                percent,
+                threshold,
+                open = usage.map_or(0, |usage| usage.open),
+                "process file descriptor usage above warning threshold"
+            );
+        }
+    }
+
+    /// Returns a point-in-time copy of the guard counters.
+    pub(crate) fn snapshot(&self) -> FdExhaustionSnapshot {
+        let state = self.state.lock();
+        FdExhaustionSnapshot {
+            exhaustion_events: self.exhaustion_events.load(Ordering::Relaxed),
+            emergency_sheds: self.emergency_sheds.load(Ordering::Relaxed),
+            events_emitted: self.events_emitted.load(Ordering::Relaxed),
+            events_suppressed: self.events_suppressed.load(Ordering::Relaxed),
+            usage_warnings: self.usage_warnings.load(Ordering::Relaxed),
+            emergency_fd_reserved: self.emergency.lock().is_some(),
+            cooldown_until: state.cooldown_until,
+            fd_usage: state.fd_usage,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(config: FdExhaustionConfig, probe: fn() -> Option<FdUsage>) -> AcceptFdGuard {
+        AcceptFdGuard::with_usage_probe(config, probe, || Some(EmergencyFd::new(())))
+    }
+
+    fn no_usage() -> Option<FdUsage> {
+        None
+    }
+
+    fn high_usage() -> Option<FdUsage> {
+        Some(FdUsage {
+            open: 90,
+            limit: Some(100),
+        })
+    }
+
+    #[test]
+    fn exhaustion_events_are_rate_limited() {
+        let guard = guard(FdExhaustionConfig::default(), no_usage);
+        let start = Time::from_millis(0);
+
+        let deadline = guard.on_exhaustion(start, || true, || Some(EmergencyFd::new(())));
+        assert_eq!(deadline, Time::from_millis(100));
+        guard.on_exhaustion(Time::from_millis(200), || false, || None);
+        guard.on_exhaustion(Time::from_millis(400), || false, || None);
+        guard.on_exhaustion(Time::from_millis(1_000), || false, || None);
+
+        let snap = guard.snapshot();
+        assert_eq!(snap.exhaustion_events, 4);
+        assert_eq!(snap.events_emitted, 2);
+        assert_eq!(snap.events_suppressed, 2);
+        assert_eq!(snap.emergency_sheds, 1);
+        assert!(!snap.emergency_fd_reserved, "last reacquire failed");
+    }
+
+    #[test]
+    fn cooldown_expires_on_the_runtime_clock() {
+        let guard = guard(FdExhaustionConfig::default(), no_usage);
+        let deadline = guard.on_exhaustion(Time::from_millis(10), || false, || None);
+
+        assert_eq!(
+            guard.cooldown_deadline(Time::from_millis(50)),
+            Some(deadline)
+        );
+        assert_eq!(guard.cooldown_deadline(Time::from_millis(110)), None);
+        assert_eq!(guard.snapshot().cooldown_until, None);
+    }
+
+    #[test]
+    fn emergency_fd_is_not_reserved_when_disabled() {
+        let config = FdExhaustionConfig {
+            emergency_fd: false,
+            ..FdExhaustionConfig::default()
+        };
+        let guard = guard(config, no_usage);
+        assert!(!guard.snapshot().emergency_fd_reserved);
+
+        let mut shed_called = false;
+        guard.on_exhaustion(
+            Time::ZERO,
+            || {
+                shed_called = true;
+                true
+            },
+            || Some(EmergencyFd::new(())),
+        );
+        assert!(!shed_called);
+        assert!(!guard.snapshot().emergency_fd_reserved);
+    }
+
+    #[test]
+    fn usage_warning_fires_above_threshold_and_is_sampled_per_interval() {
+        let guard = guard(FdExhaustionConfig::default(), high_usage);
+        guard.observe_usage(Time::from_millis(0));
+        guard.observe_usage(Time::from_millis(500));
+        guard.observe_usage(Time::from_millis(1_000));
+
+        let snap = guard.snapshot();
+        assert_eq!(snap.usage_warnings, 2);
+        assert_eq!(snap.fd_usage.and_then(|u| u.percent_of_limit()), Some(90));
+
+        let quiet = guard(
+            FdExhaustionConfig {
+                warning_percent: Some(95),
+                ..FdExhaustionConfig::default()
+            },
+            high_usage,
+        );
+        quiet.observe_usage(Time::ZERO);
+        assert_eq!(quiet.snapshot().usage_warnings, 0);
+    }
+}
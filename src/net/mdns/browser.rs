@@ -0,0 +1,511 @@
+//! DNS-SD browser: record cache and instance add/remove events.
+//!
+//! A [`Browser`] watches one service type. It caches PTR, SRV, TXT and
+//! address records from every response it sees, resolves them into
+//! [`ServiceInstance`]s and reports changes as [`BrowseEvent`]s.
+//!
+//! Cache rules follow RFC 6762: a record expires after its TTL (§5.2
+//! refresh queries go out at 80/85/90/95% of it), a goodbye (TTL zero)
+//! expires the record one second later (§10.1), and a record carrying the
+//! cache-flush bit expires other records of the same name and type that are
+//! more than one second old, also after one second (§10.2).
+//!
+//! Continuous queries start 20–120 ms after creation and back off from one
+//! second, doubling up to one hour (§5.2). Each query lists the PTR records
+//! already known with more than half their TTL left (§7.1).
+
+use super::responder::service_name;
+use super::wire::{Message, Name, Question, Record, RecordData, TYPE_PTR};
+use crate::types::Time;
+use crate::util::DetRng;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+const INITIAL_QUERY_MIN_MS: u64 = 20;
+const INITIAL_QUERY_JITTER_MS: u64 = 100;
+const QUERY_INTERVAL_START: Duration = Duration::from_secs(1);
+const QUERY_INTERVAL_MAX: Duration = Duration::from_secs(3600);
+const GOODBYE_GRACE: Duration = Duration::from_secs(1);
+const REFRESH_PERCENTS: [u64; 4] = [80, 85, 90, 95];
+
+/// A resolved service instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInstance {
+    /// Instance label, e.g. `arm (2)`.
+    pub name: String,
+    /// Service type, as passed to [`Browser::new`].
+    pub service_type: String,
+    /// Target host name, e.g. `node-a.local`.
+    pub host: String,
+    /// Service port.
+    pub port: u16,
+    /// Known host addresses, sorted.
+    pub addresses: Vec<IpAddr>,
+    /// TXT entries. Keys are lowercased; entries without `=` map to `""`.
+    pub txt: BTreeMap<String, String>,
+}
+
+impl ServiceInstance {
+    /// Returns the TXT value for `key` (case-insensitive).
+    #[must_use]
+    pub fn txt(&self, key: &str) -> Option<&str> {
+        self.txt.get(&key.to_ascii_lowercase()).map(String::as_str)
+    }
+}
+
+/// A change in the set of discovered instances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowseEvent {
+    /// An instance was discovered.
+    Added(ServiceInstance),
+    /// A known instance changed its location, addresses or TXT data.
+    Updated(ServiceInstance),
+    /// An instance went away (goodbye or expiry).
+    Removed {
+        /// Instance label.
+        name: String,
+        /// Service type.
+        service_type: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    record: Record,
+    received: Time,
+    expires: Time,
+    refreshes_sent: usize,
+}
+
+impl CacheEntry {
+    fn refresh_at(&self) -> Option<Time> {
+        let percent = *REFRESH_PERCENTS.get(self.refreshes_sent)?;
+        let lifetime = u64::from(self.record.ttl) * 1_000_000_000;
+        Some(self.received.saturating_add_nanos(lifetime / 100 * percent))
+    }
+}
+
+/// Discovers instances of one service type.
+#[derive(Debug)]
+pub struct Browser {
+    service_type: String,
+    service: Name,
+    cache: Vec<CacheEntry>,
+    known: BTreeMap<String, ServiceInstance>,
+    next_query: Time,
+    query_interval: Duration,
+    events: Vec<BrowseEvent>,
+}
+
+impl Browser {
+    /// Starts browsing `service_type` (e.g. `_robot._udp`) at `now`.
+    #[must_use]
+    pub fn new(service_type: impl Into<String>, now: Time, seed: u64) -> Self {
+        let service_type = service_type.into();
+        let service = service_name(&service_type);
+        let jitter = DetRng::new(seed).next_u64() % INITIAL_QUERY_JITTER_MS;
+        Self {
+            service_type,
+            service,
+            cache: Vec::new(),
+            known: BTreeMap::new(),
+            next_query: now + Duration::from_millis(INITIAL_QUERY_MIN_MS + jitter),
+            query_interval: QUERY_INTERVAL_START,
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the browsed service type.
+    #[must_use]
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// Returns the instances currently known, ordered by name.
+    pub fn instances(&self) -> impl Iterator<Item = &ServiceInstance> {
+        self.known.values()
+    }
+
+    /// Takes the events produced since the last call.
+    pub fn take_events(&mut self) -> Vec<BrowseEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns when [`Browser::tick`] next has work to do.
+    #[must_use]
+    pub fn poll_timeout(&self) -> Time {
+        self.cache
+            .iter()
+            .flat_map(|entry| [Some(entry.expires), entry.refresh_at()])
+            .flatten()
+            .fold(self.next_query, Time::min)
+    }
+
+    /// Expires stale records and returns the query to send, if one is due.
+    pub fn tick(&mut self, now: Time) -> Option<Message> {
+        self.expire(now);
+        let mut refresh_due = false;
+        for entry in &mut self.cache {
+            while entry.refresh_at().is_some_and(|at| at <= now) {
+                entry.refreshes_sent += 1;
+                refresh_due = true;
+            }
+        }
+        if now >= self.next_query || refresh_due {
+            if now >= self.next_query {
+                self.next_query = now + self.query_interval;
+                self.query_interval = (self.query_interval * 2).min(QUERY_INTERVAL_MAX);
+            }
+            let mut query = Message::query();
+            query.questions.push(Question::new(self.service.clone(), TYPE_PTR));
+            query.answers = self
+                .cache
+                .iter()
+                .filter(|entry| {
+                    entry.record.rtype() == TYPE_PTR
+                        && entry.record.name == self.service
+                        && entry.expires.duration_since(now)
+                            > u64::from(entry.record.ttl) * 500_000_000
+                })
+                .map(|entry| {
+                    let mut record = entry.record.clone();
+                    record.ttl = u32::try_from(entry.expires.duration_since(now) / 1_000_000_000)
+                        .unwrap_or(u32::MAX);
+                    record
+                })
+                .collect();
+            return Some(query);
+        }
+        None
+    }
+
+    /// Caches the relevant records of a response.
+    pub fn handle(&mut self, message: &Message, now: Time) {
+        if !message.response {
+            return;
+        }
+        let mut changed = false;
+        let records: Vec<&Record> = message.records().collect();
+        // Instance records first so address records can be matched against
+        // SRV targets carried in the same message.
+        for record in &records {
+            if self.is_instance_record(record) {
+                changed |= self.insert(record, now);
+            }
+        }
+        for record in &records {
+            if matches!(record.data, RecordData::A(_) | RecordData::Aaaa(_))
+                && self.is_known_target(&record.name)
+            {
+                changed |= self.insert(record, now);
+            }
+        }
+        if changed {
+            self.reconcile(now);
+        }
+    }
+
+    fn is_instance_record(&self, record: &Record) -> bool {
+        match &record.data {
+            RecordData::Ptr(target) => {
+                record.name == self.service && target.child_label_of(&self.service).is_some()
+            }
+            RecordData::Srv { .. } | RecordData::Txt(_) => {
+                record.name.child_label_of(&self.service).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn is_known_target(&self, host: &Name) -> bool {
+        self.cache.iter().any(|entry| match &entry.record.data {
+            RecordData::Srv { target, .. } => target == host,
+            _ => false,
+        })
+    }
+
+    /// Inserts or refreshes a record; returns `true` if the cache changed in
+    /// a way that can affect resolution.
+    fn insert(&mut self, record: &Record, now: Time) -> bool {
+        if record.cache_flush {
+            for entry in &mut self.cache {
+                if entry.record.name == record.name
+                    && entry.record.rtype() == record.rtype()
+                    && entry.record.data != record.data
+                    && now.duration_since(entry.received) > 1_000_000_000
+                {
+                    entry.expires = entry.expires.min(now + GOODBYE_GRACE);
+                }
+            }
+        }
+        let expires = if record.ttl == 0 {
+            now + GOODBYE_GRACE
+        } else {
+            now + Duration::from_secs(u64::from(record.ttl))
+        };
+        if let Some(entry) = self.cache.iter_mut().find(|entry| {
+            entry.record.name == record.name && entry.record.data == record.data
+        }) {
+            if record.ttl == 0 {
+                entry.expires = entry.expires.min(expires);
+            } else {
+                entry.record.ttl = record.ttl;
+                entry.received = now;
+                entry.expires = expires;
+                entry.refreshes_sent = 0;
+            }
+            false
+        } else if record.ttl > 0 {
+            self.cache.push(CacheEntry {
+                record: record.clone(),
+                received: now,
+                expires,
+                refreshes_sent: 0,
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expire(&mut self, now: Time) {
+        let before = self.cache.len();
+        self.cache.retain(|entry| entry.expires > now);
+        if self.cache.len() != before {
+            self.reconcile(now);
+        }
+    }
+
+    fn reconcile(&mut self, now: Time) {
+        let live = |entry: &&CacheEntry| entry.expires > now;
+        let mut resolved = BTreeMap::new();
+        for entry in self.cache.iter().filter(live) {
+            let RecordData::Ptr(instance) = &entry.record.data else {
+                continue;
+            };
+            let Some(label) = instance.child_label_of(&self.service) else {
+                continue;
+            };
+            let mut srv = None;
+            let mut txt = BTreeMap::new();
+            for entry in self.cache.iter().filter(live) {
+                if entry.record.name != *instance {
+                    continue;
+                }
+                match &entry.record.data {
+                    RecordData::Srv { port, target, .. } => srv = Some((*port, target.clone())),
+                    RecordData::Txt(strings) => txt = parse_txt(strings),
+                    _ => {}
+                }
+            }
+            let Some((port, target)) = srv else {
+                continue;
+            };
+            let mut addresses: Vec<IpAddr> = self
+                .cache
+                .iter()
+                .filter(live)
+                .filter(|entry| entry.record.name == target)
+                .filter_map(|entry| match entry.record.data {
+                    RecordData::A(v4) => Some(IpAddr::V4(v4)),
+                    RecordData::Aaaa(v6) => Some(IpAddr::V6(v6)),
+                    _ => None,
+                })
+                .collect();
+            addresses.sort_unstable();
+            addresses.dedup();
+            resolved.insert(
+                label.to_ascii_lowercase(),
+                ServiceInstance {
+                    name: label.to_string(),
+                    service_type: self.service_type.clone(),
+                    host: target.to_string(),
+                    port,
+                    addresses,
+                    txt,
+                },
+            );
+        }
+
+        for (key, old) in &self.known {
+            if !resolved.contains_key(key) {
+                self.events.push(BrowseEvent::Removed {
+                    name: old.name.clone(),
+                    service_type: self.service_type.clone(),
+                });
+            }
+        }
+        for (key, instance) in &resolved {
+            match self.known.get(key) {
+                None => self.events.push(BrowseEvent::Added(instance.clone())),
+                Some(old) if old != instance => {
+                    self.events.push(BrowseEvent::Updated(instance.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        self.known = resolved;
+    }
+}
+
+fn parse_txt(strings: &[Vec<u8>]) -> BTreeMap<String, String> {
+    let mut txt = BTreeMap::new();
+    for string in strings {
+        let string = String::from_utf8_lossy(string);
+        let (key, value) = string.split_once('=').unwrap_or((&*string, ""));
+        if key.is_empty() {
+            continue;
+        }
+        // The first occurrence of a key wins (RFC 6763 §6.4).
+        txt.entry(key.to_ascii_lowercase())
+            .or_insert_with(|| value.to_string());
+    }
+    txt
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn announcement(label: &str, port: u16, ttl: u32, txt: &[&str]) -> Message {
+        let service = service_name("_robot._udp");
+        let instance = service.prepend(label);
+        let host = Name::parse("node-a.local");
+        let mut message = Message::response();
+        message.answers.push(Record {
+            name: service,
+            cache_flush: false,
+            ttl,
+            data: RecordData::Ptr(instance.clone()),
+        });
+        message.answers.push(Record {
+            name: instance.clone(),
+            cache_flush: true,
+            ttl,
+            data: RecordData::Srv {
+                priority: 0,
+                weight: 0,
+                port,
+                target: host.clone(),
+            },
+        });
+        message.answers.push(Record {
+            name: instance,
+            cache_flush: true,
+            ttl,
+            data: RecordData::Txt(txt.iter().map(|s| s.as_bytes().to_vec()).collect()),
+        });
+        message.additionals.push(Record {
+            name: host,
+            cache_flush: true,
+            ttl,
+            data: RecordData::A(Ipv4Addr::new(10, 0, 0, 1)),
+        });
+        message
+    }
+
+    #[test]
+    fn resolves_added_updated_and_goodbye() {
+        init_test("resolves_added_updated_and_goodbye");
+        let mut browser = Browser::new("_robot._udp", Time::ZERO, 3);
+        let first = announcement("Arm", 7000, 120, &["Schema=3", "schema=9", "flag"]);
+        browser.handle(&first, Time::ZERO);
+        let events = browser.take_events();
+        assert_eq!(events.len(), 1);
+        let BrowseEvent::Added(instance) = &events[0] else {
+            panic!("expected Added, got {events:?}");
+        };
+        assert_eq!(instance.name, "Arm");
+        assert_eq!(instance.host, "node-a.local");
+        assert_eq!(instance.port, 7000);
+        assert_eq!(instance.addresses, vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))]);
+        assert_eq!(instance.txt("SCHEMA"), Some("3"));
+        assert_eq!(instance.txt("flag"), Some(""));
+
+        // A repeated announcement is not an event.
+        let t1 = Time::from_secs(2);
+        browser.handle(&announcement("Arm", 7000, 120, &["schema=3", "flag"]), t1);
+        assert!(browser.take_events().is_empty());
+
+        // New SRV with the cache-flush bit replaces the old one a second later.
+        let t2 = Time::from_secs(4);
+        browser.handle(&announcement("Arm", 7001, 120, &["schema=3", "flag"]), t2);
+        browser.tick(t2 + Duration::from_millis(1001));
+        let events = browser.take_events();
+        assert!(
+            matches!(events.last(), Some(BrowseEvent::Updated(instance)) if instance.port == 7001),
+            "{events:?}"
+        );
+
+        let t3 = Time::from_secs(10);
+        browser.handle(&announcement("Arm", 7001, 0, &["schema=3", "flag"]), t3);
+        browser.tick(t3 + Duration::from_millis(999));
+        assert!(browser.take_events().is_empty(), "goodbye honours the 1s grace");
+        browser.tick(t3 + GOODBYE_GRACE);
+        assert_eq!(
+            browser.take_events(),
+            vec![BrowseEvent::Removed {
+                name: "Arm".into(),
+                service_type: "_robot._udp".into(),
+            }]
+        );
+        assert_eq!(browser.instances().count(), 0);
+        crate::test_complete!("resolves_added_updated_and_goodbye");
+    }
+
+    #[test]
+    fn queries_back_off_and_refresh_before_expiry() {
+        init_test("queries_back_off_and_refresh_before_expiry");
+        let mut browser = Browser::new("_robot._udp", Time::ZERO, 3);
+        let first = browser.poll_timeout();
+        assert!(first >= Time::from_millis(20) && first < Time::from_millis(120));
+        let query = browser.tick(first).expect("initial query");
+        assert!(query.answers.is_empty());
+        assert_eq!(browser.poll_timeout(), first + Duration::from_secs(1));
+
+        let now = first + Duration::from_millis(10);
+        browser.handle(&announcement("Arm", 7000, 10, &[]), now);
+        browser.take_events();
+        let query = browser.tick(first + Duration::from_secs(1)).expect("second query");
+        assert_eq!(query.answers.len(), 1, "known answer carried");
+
+        // Refresh queries at 80% of the 10s TTL, then expiry without goodbye.
+        assert!(browser.tick(now + Duration::from_secs(8)).is_some());
+        assert!(browser.cache.iter().all(|entry| entry.refreshes_sent == 1));
+        assert!(browser.take_events().is_empty());
+        browser.tick(now + Duration::from_secs(10));
+        assert!(matches!(
+            browser.take_events().as_slice(),
+            [BrowseEvent::Removed { name, .. }] if name == "Arm"
+        ));
+        crate::test_complete!("queries_back_off_and_refresh_before_expiry");
+    }
+
+    #[test]
+    fn ignores_other_services_and_queries() {
+        init_test("ignores_other_services_and_queries");
+        let mut browser = Browser::new("_other._tcp", Time::ZERO, 3);
+        browser.handle(&announcement("Arm", 7000, 120, &[]), Time::ZERO);
+        let mut query = announcement("Arm", 7000, 120, &[]);
+        query.response = false;
+        browser.handle(&query, Time::ZERO);
+        assert!(browser.take_events().is_empty());
+        assert!(browser.cache.is_empty());
+        crate::test_complete!("ignores_other_services_and_queries");
+    }
+}
@@ -0,0 +1,416 @@
+//! Sans-I/O mDNS engine multiplexing responders and browsers.
+//!
+//! [`MdnsEngine`] is what a node runs: it owns any number of advertised
+//! services and browsed service types, routes every received datagram to all
+//! of them, and collects their outgoing messages as encoded datagrams bound
+//! for the multicast group. It never touches a socket or a clock, so the same
+//! engine runs under [`super::service::MdnsService`] on a real interface and
+//! under [`crate::lab::SimMulticastDomain`] in deterministic tests.
+//!
+//! Received datagrams that fail to decode are dropped and counted, never
+//! propagated: the multicast group is an open, unauthenticated medium.
+
+use super::browser::{BrowseEvent, Browser, ServiceInstance};
+use super::responder::{Responder, ResponderState, ServiceInfo};
+use super::wire::{Message, WireError, decode, encode};
+use crate::types::Time;
+use crate::util::DetRng;
+use std::collections::BTreeMap;
+
+/// Handle to a service advertised through [`MdnsEngine::advertise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServiceId(u64);
+
+/// Handle to a browse started through [`MdnsEngine::browse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BrowseId(u64);
+
+/// Transport-free mDNS node: responders, browsers and their timers.
+#[derive(Debug)]
+pub struct MdnsEngine {
+    rng: DetRng,
+    next_id: u64,
+    responders: BTreeMap<ServiceId, Responder>,
+    browsers: BTreeMap<BrowseId, Browser>,
+    outgoing: Vec<Vec<u8>>,
+    malformed: u64,
+}
+
+impl MdnsEngine {
+    /// Creates an engine. `seed` drives probe and query jitter.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: DetRng::new(seed),
+            next_id: 0,
+            responders: BTreeMap::new(),
+            browsers: BTreeMap::new(),
+            outgoing: Vec::new(),
+            malformed: 0,
+        }
+    }
+
+    /// Starts advertising `info`. Probing begins on the next [`MdnsEngine::tick`].
+    pub fn advertise(&mut self, info: ServiceInfo, now: Time) -> Result<ServiceId, WireError> {
+        let responder = Responder::new(info, now, self.rng.next_u64())?;
+        let id = ServiceId(self.allocate_id());
+        self.responders.insert(id, responder);
+        Ok(id)
+    }
+
+    /// Stops advertising a service, queueing its goodbye if it was announced.
+    /// Returns `false` if the id is unknown.
+    pub fn withdraw(&mut self, id: ServiceId) -> bool {
+        let Some(mut responder) = self.responders.remove(&id) else {
+            return false;
+        };
+        if let Some(goodbye) = responder.goodbye() {
+            self.queue(&goodbye);
+        }
+        true
+    }
+
+    /// Starts browsing `service_type`, e.g. `_robot._udp`.
+    pub fn browse(&mut self, service_type: impl Into<String>, now: Time) -> BrowseId {
+        let browser = Browser::new(service_type, now, self.rng.next_u64());
+        let id = BrowseId(self.allocate_id());
+        self.browsers.insert(id, browser);
+        id
+    }
+
+    /// Stops a browse. Returns `false` if the id is unknown.
+    pub fn stop_browse(&mut self, id: BrowseId) -> bool {
+        self.browsers.remove(&id).is_some()
+    }
+
+    /// Processes one received datagram.
+    pub fn handle_datagram(&mut self, packet: &[u8], now: Time) {
+        let Ok(message) = decode(packet) else {
+            self.malformed += 1;
+            return;
+        };
+        let mut replies = Vec::new();
+        for responder in self.responders.values_mut() {
+            replies.extend(responder.handle(&message, now));
+        }
+        for browser in self.browsers.values_mut() {
+            browser.handle(&message, now);
+        }
+        for reply in &replies {
+            self.queue(reply);
+        }
+    }
+
+    /// Runs every timer due at `now`.
+    pub fn tick(&mut self, now: Time) {
+        let mut messages = Vec::new();
+        for responder in self.responders.values_mut() {
+            messages.extend(responder.tick(now));
+        }
+        for browser in self.browsers.values_mut() {
+            messages.extend(browser.tick(now));
+        }
+        for message in &messages {
+            self.queue(message);
+        }
+    }
+
+    /// Returns when [`MdnsEngine::tick`] next has work to do, or `None` when
+    /// the engine is idle.
+    #[must_use]
+    pub fn poll_timeout(&self) -> Option<Time> {
+        let responders = self.responders.values().filter_map(Responder::poll_timeout);
+        let browsers = self.browsers.values().map(Browser::poll_timeout);
+        responders.chain(browsers).min()
+    }
+
+    /// Takes the datagrams to multicast, in the order they were produced.
+    pub fn take_outgoing(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    /// Takes the events of one browse produced since the last call.
+    pub fn take_events(&mut self, id: BrowseId) -> Vec<BrowseEvent> {
+        self.browsers
+            .get_mut(&id)
+            .map(Browser::take_events)
+            .unwrap_or_default()
+    }
+
+    /// Returns the instances currently known to a browse, ordered by name.
+    #[must_use]
+    pub fn instances(&self, id: BrowseId) -> Vec<ServiceInstance> {
+        self.browsers
+            .get(&id)
+            .map(|browser| browser.instances().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the instance label a service currently uses, which differs
+    /// from the requested one after a conflict rename.
+    #[must_use]
+    pub fn instance_label(&self, id: ServiceId) -> Option<&str> {
+        self.responders.get(&id).map(Responder::instance_label)
+    }
+
+    /// Returns the lifecycle state of an advertised service.
+    #[must_use]
+    pub fn service_state(&self, id: ServiceId) -> Option<ResponderState> {
+        self.responders.get(&id).map(Responder::state)
+    }
+
+    /// Returns how many received datagrams failed to decode.
+    #[must_use]
+    pub const fn malformed_packets(&self) -> u64 {
+        self.malformed
+    }
+
+    /// Withdraws every service and stops every browse, queueing goodbyes.
+    pub fn shutdown(&mut self) {
+        let ids: Vec<ServiceId> = self.responders.keys().copied().collect();
+        for id in ids {
+            self.withdraw(id);
+        }
+        self.browsers.clear();
+    }
+
+    fn allocate_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn queue(&mut self, message: &Message) {
+        // Records were validated when the service was advertised and browse
+        // queries only carry cached, already-decoded names, so encoding cannot
+        // fail in practice; a failure drops the datagram like a lost packet.
+        if let Ok(bytes) = encode(message) {
+            self.outgoing.push(bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::bytes::Bytes;
+    use crate::lab::SimMulticastDomain;
+    use crate::lab::network::{Fault, HostId, NetworkConditions, NetworkConfig};
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    struct Node {
+        host: HostId,
+        engine: MdnsEngine,
+    }
+
+    struct Harness {
+        domain: SimMulticastDomain,
+        nodes: Vec<Node>,
+    }
+
+    impl Harness {
+        fn new(seed: u64, names: &[&str]) -> Self {
+            let config = NetworkConfig {
+                seed,
+                default_conditions: NetworkConditions::ideal(),
+                ..NetworkConfig::default()
+            };
+            let mut domain = SimMulticastDomain::new(config);
+            let nodes = names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| Node {
+                    host: domain.add_host(*name),
+                    engine: MdnsEngine::new(seed ^ (index as u64 + 1)),
+                })
+                .collect();
+            Self { domain, nodes }
+        }
+
+        /// Runs engines and network in 10ms virtual steps.
+        fn run_for(&mut self, duration: Duration) {
+            let end = self.domain.now() + duration;
+            while self.domain.now() < end {
+                let now = self.domain.now();
+                for node in &mut self.nodes {
+                    for packet in self.domain.take_inbox(node.host) {
+                        node.engine.handle_datagram(&packet.payload, now);
+                    }
+                    node.engine.tick(now);
+                    for datagram in node.engine.take_outgoing() {
+                        self.domain.send(node.host, &Bytes::from(datagram));
+                    }
+                }
+                self.domain.run_for(Duration::from_millis(10));
+            }
+        }
+    }
+
+    fn robot(instance: &str, host: &str, node: &str, last_octet: u8) -> ServiceInfo {
+        ServiceInfo::new(instance, "_robot._udp", host, 7000)
+            .with_address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)))
+            .with_txt("node", node)
+            .with_txt("schema", "3")
+    }
+
+    #[test]
+    fn two_nodes_discover_each_other() {
+        init_test("two_nodes_discover_each_other");
+        let mut harness = Harness::new(11, &["a", "b"]);
+        let now = harness.domain.now();
+        harness.nodes[0]
+            .engine
+            .advertise(robot("arm", "node-a.local", "node-a", 1), now)
+            .expect("advertise a");
+        harness.nodes[1]
+            .engine
+            .advertise(robot("base", "node-b.local", "node-b", 2), now)
+            .expect("advertise b");
+        let browse_a = harness.nodes[0].engine.browse("_robot._udp", now);
+        let browse_b = harness.nodes[1].engine.browse("_robot._udp", now);
+
+        harness.run_for(Duration::from_secs(3));
+
+        let seen_by_a = harness.nodes[0].engine.instances(browse_a);
+        assert_eq!(seen_by_a.len(), 1, "{seen_by_a:?}");
+        assert_eq!(seen_by_a[0].name, "base");
+        assert_eq!(seen_by_a[0].txt("node"), Some("node-b"));
+        assert_eq!(seen_by_a[0].addresses, vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))]);
+        let seen_by_b = harness.nodes[1].engine.instances(browse_b);
+        assert_eq!(seen_by_b.len(), 1, "{seen_by_b:?}");
+        assert_eq!(seen_by_b[0].name, "arm");
+        assert_eq!(seen_by_b[0].txt("schema"), Some("3"));
+        assert!(matches!(
+            harness.nodes[1].engine.take_events(browse_b).as_slice(),
+            [BrowseEvent::Added(instance)] if instance.port == 7000
+        ));
+        crate::test_complete!("two_nodes_discover_each_other");
+    }
+
+    #[test]
+    fn name_conflict_renames_deterministically() {
+        init_test("name_conflict_renames_deterministically");
+        let run = |seed| {
+            let mut harness = Harness::new(seed, &["a", "b", "observer"]);
+            let now = harness.domain.now();
+            let a = harness.nodes[0]
+                .engine
+                .advertise(robot("arm", "node-a.local", "node-a", 1), now)
+                .expect("advertise a");
+            harness.run_for(Duration::from_secs(3));
+            let b = harness.nodes[1]
+                .engine
+                .advertise(robot("arm", "node-b.local", "node-b", 2), harness.domain.now())
+                .expect("advertise b");
+            let now = harness.domain.now();
+            let browse = harness.nodes[2].engine.browse("_robot._udp", now);
+            harness.run_for(Duration::from_secs(5));
+            let label_a = harness.nodes[0].engine.instance_label(a).map(str::to_string);
+            let label_b = harness.nodes[1].engine.instance_label(b).map(str::to_string);
+            let names: Vec<String> = harness.nodes[2]
+                .engine
+                .instances(browse)
+                .into_iter()
+                .map(|instance| instance.name)
+                .collect();
+            (label_a, label_b, names)
+        };
+
+        let (label_a, label_b, names) = run(21);
+        assert_eq!(label_a.as_deref(), Some("arm"));
+        assert_eq!(label_b.as_deref(), Some("arm (2)"));
+        assert_eq!(names, vec!["arm".to_string(), "arm (2)".to_string()]);
+        assert_eq!(run(21), (label_a, label_b, names), "same seed, same outcome");
+        crate::test_complete!("name_conflict_renames_deterministically");
+    }
+
+    #[test]
+    fn goodbye_removes_instance_promptly() {
+        init_test("goodbye_removes_instance_promptly");
+        let mut harness = Harness::new(31, &["a", "b"]);
+        let now = harness.domain.now();
+        let service = harness.nodes[0]
+            .engine
+            .advertise(robot("arm", "node-a.local", "node-a", 1), now)
+            .expect("advertise");
+        let browse = harness.nodes[1].engine.browse("_robot._udp", now);
+        harness.run_for(Duration::from_secs(3));
+        assert_eq!(harness.nodes[1].engine.instances(browse).len(), 1);
+        harness.nodes[1].engine.take_events(browse);
+
+        assert!(harness.nodes[0].engine.withdraw(service));
+        harness.run_for(Duration::from_millis(1100));
+        assert!(harness.nodes[1].engine.instances(browse).is_empty());
+        assert!(matches!(
+            harness.nodes[1].engine.take_events(browse).as_slice(),
+            [BrowseEvent::Removed { name, .. }] if name == "arm"
+        ));
+        crate::test_complete!("goodbye_removes_instance_promptly");
+    }
+
+    #[test]
+    fn crashed_node_expires_after_ttl() {
+        init_test("crashed_node_expires_after_ttl");
+        let mut harness = Harness::new(41, &["a", "b"]);
+        let now = harness.domain.now();
+        harness.nodes[0]
+            .engine
+            .advertise(robot("arm", "node-a.local", "node-a", 1).with_ttl(10), now)
+            .expect("advertise");
+        let browse = harness.nodes[1].engine.browse("_robot._udp", now);
+        harness.run_for(Duration::from_secs(3));
+        assert_eq!(harness.nodes[1].engine.instances(browse).len(), 1);
+
+        let host = harness.nodes[0].host;
+        harness.domain.inject_fault(&Fault::HostCrash { host });
+        // The last announcement went out before t=2s with a 10s TTL.
+        harness.run_for(Duration::from_secs(6));
+        assert_eq!(harness.nodes[1].engine.instances(browse).len(), 1);
+        harness.run_for(Duration::from_secs(4));
+        assert!(harness.nodes[1].engine.instances(browse).is_empty());
+        crate::test_complete!("crashed_node_expires_after_ttl");
+    }
+
+    #[test]
+    fn malformed_packets_are_counted_and_ignored() {
+        init_test("malformed_packets_are_counted_and_ignored");
+        let mut harness = Harness::new(51, &["a", "b", "attacker"]);
+        let now = harness.domain.now();
+        harness.nodes[0]
+            .engine
+            .advertise(robot("arm", "node-a.local", "node-a", 1), now)
+            .expect("advertise");
+        let browse = harness.nodes[1].engine.browse("_robot._udp", now);
+        let attacker = harness.nodes[2].host;
+        let garbage: [&[u8]; 4] = [
+            b"",
+            &[0; 11],
+            // One question whose name is a pointer to itself.
+            &[0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0xC0, 12, 0, 12, 0, 1],
+            // A response claiming 0xFFFF answers with none present.
+            &[0, 0, 0x84, 0, 0, 0, 0xFF, 0xFF, 0, 0, 0, 0],
+        ];
+        for packet in garbage {
+            harness.domain.send(attacker, &Bytes::copy_from_slice(packet));
+        }
+        harness.run_for(Duration::from_secs(3));
+
+        assert_eq!(harness.nodes[0].engine.malformed_packets(), 4);
+        assert_eq!(harness.nodes[1].engine.malformed_packets(), 4);
+        assert_eq!(harness.nodes[1].engine.instances(browse).len(), 1);
+        crate::test_complete!("malformed_packets_are_counted_and_ignored");
+    }
+}
@@ -0,0 +1,156 @@
+//! Multicast DNS (RFC 6762) and DNS-Based Service Discovery (RFC 6763).
+//!
+//! Lets nodes on the same link find each other without a seed list: each node
+//! advertises a service instance (SRV, TXT, PTR and address records) and
+//! browses for the instances of the same service type advertised by others.
+//!
+//! # Layering
+//!
+//! - [`wire`] — the message codec. Total over arbitrary input.
+//! - [`responder`] — one advertised instance: probing, conflict renaming,
+//!   announcing, answering queries, goodbye.
+//! - [`browser`] — one browsed service type: continuous querying, the record
+//!   cache and its expiry rules, instance add/update/remove events.
+//! - [`engine`] — the per-node multiplexer, sans I/O and clock-free, which is
+//!   what lab tests drive over [`crate::lab::SimMulticastDomain`].
+//! - `service` — the async driver over a real multicast socket (not on wasm).
+//!
+//! # Membership
+//!
+//! Discovery only introduces peers; it does not judge liveness. Feed browse
+//! events to [`apply_to_membership`] and let SWIM's failure detector decide
+//! when a peer is gone. An instance that disappears from mDNS may still be
+//! reachable, and vice versa.
+//!
+//! Only IPv4 multicast is driven by the async service. IPv6 addresses are
+//! advertised and resolved like any other record.
+
+pub mod browser;
+pub mod engine;
+pub mod responder;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod service;
+pub mod wire;
+
+pub use browser::{BrowseEvent, Browser, ServiceInstance};
+pub use engine::{BrowseId, MdnsEngine, ServiceId};
+pub use responder::{DEFAULT_TTL, Responder, ResponderState, ServiceInfo};
+#[cfg(not(target_arch = "wasm32"))]
+pub use service::{BrowseStream, MdnsHandle, MdnsService, bind_multicast_v4};
+pub use wire::WireError;
+
+use crate::distributed::membership::{Millis, Swim};
+use crate::remote::NodeId;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// The mDNS UDP port.
+pub const MDNS_PORT: u16 = 5353;
+/// The mDNS IPv4 multicast group.
+pub const MDNS_IPV4: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+/// The mDNS IPv6 multicast group.
+pub const MDNS_IPV6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+/// TXT key carrying the advertiser's membership [`NodeId`].
+pub const NODE_ID_TXT_KEY: &str = "node";
+
+/// A discovered peer: its node id and the addresses it can be reached on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredPeer {
+    /// Node id from the `node` TXT entry, or the instance label without one.
+    pub node: NodeId,
+    /// The instance's addresses paired with its advertised port.
+    pub addrs: Vec<SocketAddr>,
+}
+
+impl DiscoveredPeer {
+    /// Derives the peer behind a discovered instance.
+    #[must_use]
+    pub fn from_instance(instance: &ServiceInstance) -> Self {
+        let node = instance
+            .txt(NODE_ID_TXT_KEY)
+            .filter(|id| !id.is_empty())
+            .unwrap_or(instance.name.as_str());
+        Self {
+            node: NodeId::new(node),
+            addrs: instance
+                .addresses
+                .iter()
+                .map(|&ip| SocketAddr::new(ip, instance.port))
+                .collect(),
+        }
+    }
+}
+
+/// Introduces a discovered instance to SWIM as a peer.
+///
+/// Returns the peer for `Added` and `Updated` events so the caller can record
+/// its current addresses in the transport's address book. `Removed` returns
+/// `None` and leaves the membership view untouched.
+pub fn apply_to_membership(
+    swim: &mut Swim,
+    now: Millis,
+    event: &BrowseEvent,
+) -> Option<DiscoveredPeer> {
+    let (BrowseEvent::Added(instance) | BrowseEvent::Updated(instance)) = event else {
+        return None;
+    };
+    let peer = DiscoveredPeer::from_instance(instance);
+    swim.add_peer(now, peer.node.clone());
+    Some(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::distributed::membership::SwimConfig;
+    use std::collections::BTreeMap;
+    use std::net::IpAddr;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn instance(name: &str, txt: &[(&str, &str)]) -> ServiceInstance {
+        ServiceInstance {
+            name: name.into(),
+            service_type: "_robot._udp".into(),
+            host: "node-b.local".into(),
+            port: 7000,
+            addresses: vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))],
+            txt: txt
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn browse_events_introduce_swim_peers() {
+        init_test("browse_events_introduce_swim_peers");
+        let mut swim = Swim::new(NodeId::new("node-a"), SwimConfig::default(), 1);
+        let added = BrowseEvent::Added(instance("base", &[("node", "node-b")]));
+        let peer = apply_to_membership(&mut swim, 0, &added).expect("added peer");
+        assert_eq!(peer.node, NodeId::new("node-b"));
+        assert_eq!(peer.addrs, vec!["10.0.0.2:7000".parse().unwrap()]);
+        assert_eq!(swim.alive_peers(), vec![NodeId::new("node-b")]);
+
+        let fallback = DiscoveredPeer::from_instance(&instance("base", &[]));
+        assert_eq!(fallback.node, NodeId::new("base"));
+
+        let removed = BrowseEvent::Removed {
+            name: "base".into(),
+            service_type: "_robot._udp".into(),
+        };
+        assert!(apply_to_membership(&mut swim, 10, &removed).is_none());
+        assert_eq!(swim.alive_peers(), vec![NodeId::new("node-b")]);
+        crate::test_complete!("browse_events_introduce_swim_peers");
+    }
+}
@@ -0,0 +1,613 @@
+//! DNS-SD service responder: probing, announcing, answering, goodbye.
+//!
+//! A [`Responder`] owns one advertised service instance and is driven by
+//! [`Responder::handle`] for every received message and [`Responder::tick`]
+//! whenever [`Responder::poll_timeout`] elapses. It performs no I/O; every
+//! call returns the messages to multicast.
+//!
+//! Timing follows RFC 6762: a random 0–250 ms delay, three probes 250 ms
+//! apart (§8.1), then two announcements one second apart (§8.3). A conflict
+//! seen while probing, or a differing unique record seen afterwards (§9),
+//! renames the instance deterministically (`name`, `name (2)`, `name (3)`,
+//! ...) and restarts probing. A simultaneous probe for the same name is
+//! resolved by comparing the proposed records (§8.2); the loser waits one
+//! second and probes again.
+//!
+//! Only the instance name (SRV and TXT records) is probed. The host name is
+//! expected to be unique per node, e.g. derived from its node id.
+
+use super::wire::{
+    Message, Name, Question, Record, RecordData, TYPE_ANY, TYPE_PTR, TYPE_SRV, TYPE_TXT, WireError,
+    encode,
+};
+use crate::types::Time;
+use crate::util::DetRng;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Default TTL of advertised records, in seconds.
+pub const DEFAULT_TTL: u32 = 120;
+
+const PROBE_COUNT: u8 = 3;
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+const PROBE_MAX_INITIAL_DELAY_MS: u64 = 250;
+const PROBE_LOST_DELAY: Duration = Duration::from_secs(1);
+const ANNOUNCE_COUNT: u8 = 2;
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+const SERVICES_ENUMERATION: &str = "_services._dns-sd._udp.local";
+
+/// A service instance to advertise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInfo {
+    instance: String,
+    service_type: String,
+    host: String,
+    port: u16,
+    addresses: Vec<IpAddr>,
+    txt: Vec<(String, String)>,
+    ttl: u32,
+}
+
+impl ServiceInfo {
+    /// Describes instance `instance` of `service_type` (e.g. `_robot._udp`)
+    /// served on `host.local:port`.
+    #[must_use]
+    pub fn new(
+        instance: impl Into<String>,
+        service_type: impl Into<String>,
+        host: impl Into<String>,
+        port: u16,
+    ) -> Self {
+        Self {
+            instance: instance.into(),
+            service_type: service_type.into(),
+            host: host.into(),
+            port,
+            addresses: Vec::new(),
+            txt: Vec::new(),
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Adds an address record for the host.
+    #[must_use]
+    pub fn with_address(mut self, addr: IpAddr) -> Self {
+        self.addresses.push(addr);
+        self
+    }
+
+    /// Adds a `key=value` TXT entry, replacing any earlier value for `key`.
+    #[must_use]
+    pub fn with_txt(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        let value = value.into();
+        if let Some(entry) = self.txt.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(&key)) {
+            entry.1 = value;
+        } else {
+            self.txt.push((key, value));
+        }
+        self
+    }
+
+    /// Sets the TTL of every advertised record, in seconds.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the requested instance name.
+    #[must_use]
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    /// Returns the service type.
+    #[must_use]
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// Returns the host label.
+    #[must_use]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the service port.
+    #[must_use]
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the TXT entries in insertion order.
+    #[must_use]
+    pub fn txt(&self) -> &[(String, String)] {
+        &self.txt
+    }
+
+    /// Returns the record TTL in seconds.
+    #[must_use]
+    pub const fn ttl(&self) -> u32 {
+        self.ttl
+    }
+}
+
+/// Fully-qualified name of a service type in the `.local` domain.
+pub(crate) fn service_name(service_type: &str) -> Name {
+    let mut name = Name::parse(service_type);
+    if !name.labels().last().is_some_and(|label| label.eq_ignore_ascii_case("local")) {
+        name = Name::from_labels(name.labels().iter().cloned().chain(["local".to_string()]));
+    }
+    name
+}
+
+/// Lifecycle of a [`Responder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponderState {
+    /// Checking that the instance name is unused.
+    Probing,
+    /// Name claimed; announcing the records.
+    Announcing,
+    /// Announced; answering queries.
+    Established,
+    /// Withdrawn; goodbye sent if the records were ever announced.
+    Stopped,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    Probing { sent: u8, next_at: Time },
+    Announcing { sent: u8, next_at: Time },
+    Established,
+    Stopped,
+}
+
+/// Advertises one service instance.
+#[derive(Debug)]
+pub struct Responder {
+    info: ServiceInfo,
+    service: Name,
+    host: Name,
+    instance: Name,
+    renames: u32,
+    phase: Phase,
+    rng: DetRng,
+}
+
+impl Responder {
+    /// Starts probing for `info` at `now`.
+    ///
+    /// `seed` drives the initial probe delay. Fails if a name in `info` is not
+    /// encodable.
+    pub fn new(info: ServiceInfo, now: Time, seed: u64) -> Result<Self, WireError> {
+        let service = service_name(&info.service_type);
+        let host = Name::from_labels([info.host.clone(), "local".to_string()]);
+        let instance = service.prepend(info.instance.clone());
+        let mut rng = DetRng::new(seed);
+        let delay = Duration::from_millis(rng.next_u64() % PROBE_MAX_INITIAL_DELAY_MS);
+        let responder = Self {
+            info,
+            service,
+            host,
+            instance,
+            renames: 0,
+            phase: Phase::Probing {
+                sent: 0,
+                next_at: now + delay,
+            },
+            rng,
+        };
+        encode(&responder.announcement(responder.info.ttl))?;
+        Ok(responder)
+    }
+
+    /// Returns the advertised service description.
+    #[must_use]
+    pub const fn info(&self) -> &ServiceInfo {
+        &self.info
+    }
+
+    /// Returns the instance label currently claimed (after any renames).
+    #[must_use]
+    pub fn instance_label(&self) -> &str {
+        &self.instance.labels()[0]
+    }
+
+    /// Returns the fully-qualified instance name.
+    #[must_use]
+    pub const fn instance_name(&self) -> &Name {
+        &self.instance
+    }
+
+    /// Returns the lifecycle state.
+    #[must_use]
+    pub const fn state(&self) -> ResponderState {
+        match self.phase {
+            Phase::Probing { .. } => ResponderState::Probing,
+            Phase::Announcing { .. } => ResponderState::Announcing,
+            Phase::Established => ResponderState::Established,
+            Phase::Stopped => ResponderState::Stopped,
+        }
+    }
+
+    /// Returns when [`Responder::tick`] next has work to do.
+    #[must_use]
+    pub const fn poll_timeout(&self) -> Option<Time> {
+        match self.phase {
+            Phase::Probing { next_at, .. } | Phase::Announcing { next_at, .. } => Some(next_at),
+            Phase::Established | Phase::Stopped => None,
+        }
+    }
+
+    /// Sends due probes and announcements.
+    pub fn tick(&mut self, now: Time) -> Vec<Message> {
+        let mut out = Vec::new();
+        loop {
+            match self.phase {
+                Phase::Probing { sent, next_at } if now >= next_at => {
+                    if sent < PROBE_COUNT {
+                        out.push(self.probe());
+                        self.phase = Phase::Probing {
+                            sent: sent + 1,
+                            next_at: now + PROBE_INTERVAL,
+                        };
+                        break;
+                    }
+                    self.phase = Phase::Announcing { sent: 0, next_at };
+                }
+                Phase::Announcing { sent, next_at } if now >= next_at => {
+                    out.push(self.announcement(self.info.ttl));
+                    self.phase = if sent + 1 < ANNOUNCE_COUNT {
+                        Phase::Announcing {
+                            sent: sent + 1,
+                            next_at: now + ANNOUNCE_INTERVAL * (1 << sent),
+                        }
+                    } else {
+                        Phase::Established
+                    };
+                    break;
+                }
+                _ => break,
+            }
+        }
+        out
+    }
+
+    /// Processes a received message and returns the responses to send.
+    pub fn handle(&mut self, message: &Message, now: Time) -> Vec<Message> {
+        match self.phase {
+            Phase::Stopped => Vec::new(),
+            Phase::Probing { .. } if message.response => {
+                if message.records().any(|record| record.name == self.instance) {
+                    self.rename(now);
+                }
+                Vec::new()
+            }
+            Phase::Probing { .. } => {
+                self.resolve_simultaneous_probe(message, now);
+                Vec::new()
+            }
+            Phase::Announcing { .. } | Phase::Established if message.response => {
+                let ours = self.unique_records();
+                let conflict = message.records().any(|record| {
+                    record.name == self.instance
+                        && matches!(record.rtype(), TYPE_SRV | TYPE_TXT)
+                        && record.ttl > 0
+                        && !ours.iter().any(|own| own.data == record.data)
+                });
+                if conflict {
+                    self.rename(now);
+                }
+                Vec::new()
+            }
+            Phase::Announcing { .. } | Phase::Established => {
+                self.answer(message).into_iter().collect()
+            }
+        }
+    }
+
+    /// Withdraws the service. Returns the goodbye message (all records with
+    /// TTL zero) if the records were announced.
+    pub fn goodbye(&mut self) -> Option<Message> {
+        let announced = matches!(
+            self.phase,
+            Phase::Announcing { sent: 1.., .. } | Phase::Established
+        );
+        self.phase = Phase::Stopped;
+        announced.then(|| self.announcement(0))
+    }
+
+    fn rename(&mut self, now: Time) {
+        self.renames += 1;
+        let label = format!("{} ({})", self.info.instance, self.renames + 1);
+        self.instance = self.service.prepend(label);
+        self.phase = Phase::Probing {
+            sent: 0,
+            next_at: now,
+        };
+    }
+
+    fn resolve_simultaneous_probe(&mut self, message: &Message, now: Time) {
+        let key = |record: &Record| (record.rtype(), record.data.to_bytes().unwrap_or_default());
+        let mut theirs: Vec<_> = message
+            .authorities
+            .iter()
+            .filter(|record| record.name == self.instance)
+            .map(key)
+            .collect();
+        if theirs.is_empty() {
+            return;
+        }
+        let mut ours: Vec<_> = self.instance_records(self.info.ttl).iter().map(key).collect();
+        ours.sort();
+        theirs.sort();
+        if theirs > ours {
+            // Lost the tie-break: defer and probe again with the same name.
+            let jitter = Duration::from_millis(self.rng.next_u64() % PROBE_MAX_INITIAL_DELAY_MS);
+            self.phase = Phase::Probing {
+                sent: 0,
+                next_at: now + PROBE_LOST_DELAY + jitter,
+            };
+        }
+    }
+
+    fn answer(&self, query: &Message) -> Option<Message> {
+        let mut response = Message::response();
+        let ttl = self.info.ttl;
+        for question in &query.questions {
+            let any = question.qtype == TYPE_ANY;
+            if question.name == self.service && (any || question.qtype == TYPE_PTR) {
+                let ptr = self.ptr_record(ttl);
+                let known = query
+                    .answers
+                    .iter()
+                    .any(|known| known.data == ptr.data && known.ttl >= ttl / 2);
+                if !known {
+                    push_unique(&mut response.answers, ptr);
+                    for record in self.unique_records() {
+                        push_unique(&mut response.additionals, record);
+                    }
+                }
+            } else if question.name == self.instance {
+                for record in self.instance_records(ttl) {
+                    if any || question.qtype == record.rtype() {
+                        push_unique(&mut response.answers, record);
+                    }
+                }
+                for record in self.address_records(ttl) {
+                    push_unique(&mut response.additionals, record);
+                }
+            } else if question.name == self.host {
+                for record in self.address_records(ttl) {
+                    if any || question.qtype == record.rtype() {
+                        push_unique(&mut response.answers, record);
+                    }
+                }
+            } else if question.name == Name::parse(SERVICES_ENUMERATION)
+                && (any || question.qtype == TYPE_PTR)
+            {
+                push_unique(
+                    &mut response.answers,
+                    Record {
+                        name: question.name.clone(),
+                        cache_flush: false,
+                        ttl,
+                        data: RecordData::Ptr(self.service.clone()),
+                    },
+                );
+            }
+        }
+        response
+            .additionals
+            .retain(|record| !response.answers.contains(record));
+        (!response.answers.is_empty()).then_some(response)
+    }
+
+    fn probe(&self) -> Message {
+        let mut probe = Message::query();
+        probe.questions.push(Question::new(self.instance.clone(), TYPE_ANY));
+        probe.authorities = self.instance_records(self.info.ttl);
+        for record in &mut probe.authorities {
+            record.cache_flush = false;
+        }
+        probe
+    }
+
+    fn announcement(&self, ttl: u32) -> Message {
+        let mut message = Message::response();
+        message.answers.push(self.ptr_record(ttl));
+        message.answers.extend(self.unique_records_with_ttl(ttl));
+        message
+    }
+
+    fn ptr_record(&self, ttl: u32) -> Record {
+        Record {
+            name: self.service.clone(),
+            cache_flush: false,
+            ttl,
+            data: RecordData::Ptr(self.instance.clone()),
+        }
+    }
+
+    fn unique_records(&self) -> Vec<Record> {
+        self.unique_records_with_ttl(self.info.ttl)
+    }
+
+    fn unique_records_with_ttl(&self, ttl: u32) -> Vec<Record> {
+        let mut records = self.instance_records(ttl);
+        records.extend(self.address_records(ttl));
+        records
+    }
+
+    fn instance_records(&self, ttl: u32) -> Vec<Record> {
+        let txt = self
+            .info
+            .txt
+            .iter()
+            .map(|(key, value)| format!("{key}={value}").into_bytes())
+            .collect();
+        vec![
+            Record {
+                name: self.instance.clone(),
+                cache_flush: true,
+                ttl,
+                data: RecordData::Srv {
+                    priority: 0,
+                    weight: 0,
+                    port: self.info.port,
+                    target: self.host.clone(),
+                },
+            },
+            Record {
+                name: self.instance.clone(),
+                cache_flush: true,
+                ttl,
+                data: RecordData::Txt(txt),
+            },
+        ]
+    }
+
+    fn address_records(&self, ttl: u32) -> Vec<Record> {
+        self.info
+            .addresses
+            .iter()
+            .map(|addr| Record {
+                name: self.host.clone(),
+                cache_flush: true,
+                ttl,
+                data: match addr {
+                    IpAddr::V4(v4) => RecordData::A(*v4),
+                    IpAddr::V6(v6) => RecordData::Aaaa(*v6),
+                },
+            })
+            .collect()
+    }
+}
+
+fn push_unique(records: &mut Vec<Record>, record: Record) {
+    if !records.contains(&record) {
+        records.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn info(port: u16) -> ServiceInfo {
+        ServiceInfo::new("arm", "_robot._udp", "node-a", port)
+            .with_address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+            .with_txt("schema", "3")
+    }
+
+    fn run_until_established(responder: &mut Responder, mut now: Time) -> (Vec<Message>, Time) {
+        let mut sent = Vec::new();
+        while let Some(next) = responder.poll_timeout() {
+            now = now.max(next);
+            sent.extend(responder.tick(now));
+        }
+        (sent, now)
+    }
+
+    #[test]
+    fn probes_then_announces_on_rfc_schedule() {
+        init_test("probes_then_announces_on_rfc_schedule");
+        let mut responder = Responder::new(info(7000), Time::ZERO, 7).expect("valid info");
+        let first = responder.poll_timeout().expect("probe scheduled");
+        assert!(first < Time::from_millis(250));
+
+        let mut times = Vec::new();
+        let mut kinds = Vec::new();
+        while let Some(next) = responder.poll_timeout() {
+            for message in responder.tick(next) {
+                times.push(next.duration_since(first) / 1_000_000);
+                kinds.push(message.response);
+            }
+        }
+        assert_eq!(kinds, [false, false, false, true, true]);
+        assert_eq!(times, [0, 250, 500, 750, 1750]);
+        assert_eq!(responder.state(), ResponderState::Established);
+        crate::test_complete!("probes_then_announces_on_rfc_schedule");
+    }
+
+    #[test]
+    fn answers_ptr_queries_with_known_answer_suppression() {
+        init_test("answers_ptr_queries_with_known_answer_suppression");
+        let mut responder = Responder::new(info(7000), Time::ZERO, 1).expect("valid info");
+        let (_, now) = run_until_established(&mut responder, Time::ZERO);
+
+        let mut query = Message::query();
+        query
+            .questions
+            .push(Question::new(service_name("_robot._udp"), TYPE_PTR));
+        let response = responder.handle(&query, now);
+        assert_eq!(response.len(), 1);
+        let response = &response[0];
+        assert!(!response.answers[0].cache_flush, "PTR is a shared record");
+        assert!(response.additionals.iter().all(|record| record.cache_flush));
+        assert_eq!(response.additionals.len(), 3);
+
+        query.answers.push(response.answers[0].clone());
+        assert!(responder.handle(&query, now).is_empty());
+        crate::test_complete!("answers_ptr_queries_with_known_answer_suppression");
+    }
+
+    #[test]
+    fn goodbye_only_after_announcing() {
+        init_test("goodbye_only_after_announcing");
+        let mut probing = Responder::new(info(7000), Time::ZERO, 1).expect("valid info");
+        assert!(probing.goodbye().is_none());
+
+        let mut responder = Responder::new(info(7000), Time::ZERO, 1).expect("valid info");
+        run_until_established(&mut responder, Time::ZERO);
+        let goodbye = responder.goodbye().expect("goodbye");
+        assert!(goodbye.answers.iter().all(|record| record.ttl == 0));
+        assert_eq!(responder.state(), ResponderState::Stopped);
+        crate::test_complete!("goodbye_only_after_announcing");
+    }
+
+    #[test]
+    fn conflict_after_announcing_renames() {
+        init_test("conflict_after_announcing_renames");
+        let mut responder = Responder::new(info(7000), Time::ZERO, 1).expect("valid info");
+        let (_, now) = run_until_established(&mut responder, Time::ZERO);
+
+        // Our own announcement echoed back is not a conflict.
+        let echo = responder.announcement(DEFAULT_TTL);
+        assert!(responder.handle(&echo, now).is_empty());
+        assert_eq!(responder.state(), ResponderState::Established);
+
+        let mut other = Responder::new(info(7001), Time::ZERO, 2).expect("valid info");
+        run_until_established(&mut other, Time::ZERO);
+        responder.handle(&other.announcement(DEFAULT_TTL), now);
+        assert_eq!(responder.state(), ResponderState::Probing);
+        assert_eq!(responder.instance_label(), "arm (2)");
+        crate::test_complete!("conflict_after_announcing_renames");
+    }
+
+    #[test]
+    fn rejects_unencodable_names() {
+        init_test("rejects_unencodable_names");
+        let info = ServiceInfo::new("x".repeat(64), "_robot._udp", "node-a", 1);
+        assert_eq!(
+            Responder::new(info, Time::ZERO, 0).err(),
+            Some(WireError::NameTooLong)
+        );
+        crate::test_complete!("rejects_unencodable_names");
+    }
+}
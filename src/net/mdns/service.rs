@@ -0,0 +1,291 @@
+//! Async mDNS service: drives an [`MdnsEngine`] over the IPv4 multicast group.
+//!
+//! [`MdnsService::run`] owns the socket and is meant to be spawned as a task
+//! in the region that should own discovery. Other tasks talk to it through a
+//! cloneable [`MdnsHandle`]: advertise and withdraw services, and browse a
+//! service type as a [`BrowseStream`] of [`BrowseEvent`]s.
+//!
+//! Shutdown is explicit. When `run` observes cancellation it withdraws every
+//! service and multicasts the goodbyes before returning, and
+//! [`MdnsService::goodbye_on_close`] registers the same withdrawal as a region
+//! finalizer, so peers drop the node within a second instead of waiting out
+//! the record TTL.
+
+use super::browser::BrowseEvent;
+use super::engine::{BrowseId, MdnsEngine, ServiceId};
+use super::responder::ServiceInfo;
+use super::wire::WireError;
+use super::{MDNS_IPV4, MDNS_PORT};
+use crate::cx::{Cx, Scope};
+use crate::net::UdpSocket;
+use crate::runtime::RuntimeState;
+use crate::stream::Stream;
+use crate::types::Policy;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Largest datagram accepted (RFC 6762 §17 allows up to 9000 bytes).
+const RECV_BUFFER_BYTES: usize = 9000;
+/// Upper bound on one wait for traffic, so handle calls made while the loop
+/// is idle are picked up promptly.
+const MAX_WAIT: Duration = Duration::from_millis(50);
+
+/// Binds a UDP socket to the mDNS port on all IPv4 interfaces and joins the
+/// mDNS group.
+///
+/// The port is shared (`SO_REUSEADDR`, plus `SO_REUSEPORT` on Unix) so the
+/// service coexists with a system responder. Multicast loopback stays on,
+/// which lets browsers on this host see services advertised by other
+/// processes on it.
+pub fn bind_multicast_v4() -> io::Result<UdpSocket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV4,
+        socket2::Type::DGRAM,
+        Some(socket2::Protocol::UDP),
+    )?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, MDNS_PORT));
+    socket.bind(&socket2::SockAddr::from(addr))?;
+    socket.set_nonblocking(true)?;
+    let socket = UdpSocket::from_std(socket.into())?;
+    socket.join_multicast_v4(MDNS_IPV4, Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_multicast_ttl_v4(255)?;
+    Ok(socket)
+}
+
+#[derive(Debug)]
+struct Shared {
+    engine: MdnsEngine,
+    browse_wakers: BTreeMap<BrowseId, Waker>,
+    closed: bool,
+}
+
+impl Shared {
+    fn wake_browsers(&mut self) {
+        for (_, waker) in std::mem::take(&mut self.browse_wakers) {
+            waker.wake();
+        }
+    }
+}
+
+/// Drives mDNS advertisement and discovery on one socket.
+pub struct MdnsService {
+    shared: Arc<Mutex<Shared>>,
+    socket: UdpSocket,
+    target: SocketAddr,
+    recv_buffer: Vec<u8>,
+}
+
+impl MdnsService {
+    /// Binds the standard mDNS group on all IPv4 interfaces.
+    pub fn bind(cx: &Cx) -> io::Result<Self> {
+        let socket = bind_multicast_v4()?;
+        let target = SocketAddr::V4(SocketAddrV4::new(MDNS_IPV4, MDNS_PORT));
+        Ok(Self::from_socket(socket, target, cx.random_u64()))
+    }
+
+    /// Uses a caller-bound socket, sending every datagram to `target`.
+    ///
+    /// Useful for a specific interface or a unicast test setup; the caller is
+    /// responsible for any group membership.
+    #[must_use]
+    pub fn from_socket(socket: UdpSocket, target: SocketAddr, seed: u64) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(Shared {
+                engine: MdnsEngine::new(seed),
+                browse_wakers: BTreeMap::new(),
+                closed: false,
+            })),
+            socket,
+            target,
+            recv_buffer: vec![0; RECV_BUFFER_BYTES],
+        }
+    }
+
+    /// Returns a handle for advertising and browsing.
+    #[must_use]
+    pub fn handle(&self) -> MdnsHandle {
+        MdnsHandle {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Returns how many received datagrams failed to decode.
+    #[must_use]
+    pub fn malformed_packets(&self) -> u64 {
+        self.shared.lock().engine.malformed_packets()
+    }
+
+    /// Runs until cancelled or [`MdnsHandle::shutdown`] is called, then sends
+    /// goodbyes for every advertised service.
+    ///
+    /// Malformed datagrams are dropped; only socket errors end the loop early.
+    pub async fn run(&mut self, cx: &Cx) -> io::Result<()> {
+        loop {
+            let stopping = cx.checkpoint().is_err() || self.shared.lock().closed;
+            if stopping {
+                let mut shared = self.shared.lock();
+                shared.closed = true;
+                shared.engine.shutdown();
+                shared.wake_browsers();
+                drop(shared);
+                return self.flush().await;
+            }
+            self.flush().await?;
+
+            let now = cx.now();
+            let idle_deadline = now + MAX_WAIT;
+            let deadline = self
+                .shared
+                .lock()
+                .engine
+                .poll_timeout()
+                .map_or(idle_deadline, |at| at.min(idle_deadline));
+            let received = {
+                let recv = std::pin::pin!(self.socket.recv_from(&mut self.recv_buffer));
+                match crate::time::timeout_at(deadline, recv).await {
+                    Ok(result) => Some(result?.0),
+                    Err(_elapsed) => None,
+                }
+            };
+
+            let now = cx.now();
+            let mut shared = self.shared.lock();
+            if let Some(len) = received {
+                shared.engine.handle_datagram(&self.recv_buffer[..len], now);
+            }
+            shared.engine.tick(now);
+            shared.wake_browsers();
+        }
+    }
+
+    /// Registers a finalizer on `scope` that withdraws every service and
+    /// multicasts the goodbyes when the region closes.
+    ///
+    /// Returns `Ok(false)` if the region no longer accepts finalizers.
+    pub fn goodbye_on_close<P: Policy>(
+        &self,
+        scope: &Scope<'_, P>,
+        state: &mut RuntimeState,
+    ) -> io::Result<bool> {
+        let mut socket = self.socket.try_clone()?;
+        let shared = Arc::clone(&self.shared);
+        let target = self.target;
+        Ok(scope.defer_async(state, async move {
+            let datagrams = {
+                let mut shared = shared.lock();
+                shared.closed = true;
+                shared.engine.shutdown();
+                shared.wake_browsers();
+                shared.engine.take_outgoing()
+            };
+            for datagram in datagrams {
+                // Best effort: a lost goodbye only delays removal until TTL.
+                let _ = socket.send_to(&datagram, target).await;
+            }
+        }))
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        let datagrams = self.shared.lock().engine.take_outgoing();
+        for datagram in datagrams {
+            self.socket.send_to(&datagram, self.target).await?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for MdnsService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MdnsService")
+            .field("target", &self.target)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Cloneable control handle for a running [`MdnsService`].
+#[derive(Debug, Clone)]
+pub struct MdnsHandle {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl MdnsHandle {
+    /// Starts advertising a service.
+    pub fn advertise(&self, cx: &Cx, info: ServiceInfo) -> Result<ServiceId, WireError> {
+        self.shared.lock().engine.advertise(info, cx.now())
+    }
+
+    /// Stops advertising a service and sends its goodbye.
+    pub fn withdraw(&self, id: ServiceId) -> bool {
+        self.shared.lock().engine.withdraw(id)
+    }
+
+    /// Returns the instance label a service currently uses; it differs from
+    /// the requested one after a conflict rename.
+    #[must_use]
+    pub fn instance_label(&self, id: ServiceId) -> Option<String> {
+        self.shared.lock().engine.instance_label(id).map(str::to_string)
+    }
+
+    /// Browses `service_type`. The stream ends when the service shuts down;
+    /// dropping it stops the browse.
+    #[must_use]
+    pub fn browse(&self, cx: &Cx, service_type: impl Into<String>) -> BrowseStream {
+        let id = self.shared.lock().engine.browse(service_type, cx.now());
+        BrowseStream {
+            shared: Arc::clone(&self.shared),
+            id,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Asks the service loop to send goodbyes and stop.
+    pub fn shutdown(&self) {
+        self.shared.lock().closed = true;
+    }
+}
+
+/// Stream of [`BrowseEvent`]s for one service type.
+#[derive(Debug)]
+pub struct BrowseStream {
+    shared: Arc<Mutex<Shared>>,
+    id: BrowseId,
+    pending: VecDeque<BrowseEvent>,
+}
+
+impl Stream for BrowseStream {
+    type Item = BrowseEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.pending.is_empty() {
+            let mut shared = this.shared.lock();
+            this.pending.extend(shared.engine.take_events(this.id));
+            if this.pending.is_empty() {
+                if shared.closed {
+                    return Poll::Ready(None);
+                }
+                shared.browse_wakers.insert(this.id, cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(this.pending.pop_front())
+    }
+}
+
+impl Drop for BrowseStream {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock();
+        shared.browse_wakers.remove(&self.id);
+        shared.engine.stop_browse(self.id);
+    }
+}
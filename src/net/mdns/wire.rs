@@ -0,0 +1,731 @@
+//! mDNS message codec (RFC 1035 wire format with the RFC 6762 bit reuse).
+//!
+//! Names are kept as label lists rather than dotted strings because DNS-SD
+//! instance labels are free-form UTF-8 and may themselves contain dots and
+//! spaces. Comparison is ASCII case-insensitive, as DNS requires.
+//!
+//! The decoder is total: any malformed, truncated or hostile input (pointer
+//! loops, forward pointers, oversized names, bogus counts) yields a
+//! [`WireError`], never a panic. The encoder does not compress names.
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// `A` record type.
+pub const TYPE_A: u16 = 1;
+/// `PTR` record type.
+pub const TYPE_PTR: u16 = 12;
+/// `TXT` record type.
+pub const TYPE_TXT: u16 = 16;
+/// `AAAA` record type.
+pub const TYPE_AAAA: u16 = 28;
+/// `SRV` record type.
+pub const TYPE_SRV: u16 = 33;
+/// `ANY` query type.
+pub const TYPE_ANY: u16 = 255;
+/// The Internet class.
+pub const CLASS_IN: u16 = 1;
+
+/// Cache-flush bit in a resource record's class field (RFC 6762 §10.2).
+const CACHE_FLUSH_BIT: u16 = 0x8000;
+/// Unicast-response bit in a question's class field (RFC 6762 §5.4).
+const UNICAST_RESPONSE_BIT: u16 = 0x8000;
+/// QR bit: the message is a response.
+const FLAG_RESPONSE: u16 = 0x8000;
+/// AA bit, always set on mDNS responses.
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+/// OPCODE and RCODE bits; mDNS messages with either set are ignored.
+const FLAG_OPCODE_RCODE: u16 = 0x780F;
+
+const HEADER_LEN: usize = 12;
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 255;
+const MAX_POINTER_DEPTH: usize = 16;
+
+/// Error produced by the mDNS codec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireError {
+    /// The message ended in the middle of a field.
+    Truncated,
+    /// A label or name exceeds the DNS length limits.
+    NameTooLong,
+    /// A label is empty where one is required, or uses reserved length bits.
+    InvalidLabel,
+    /// Compression pointers point forward or form a loop.
+    BadPointer,
+    /// A header field has a value mDNS must ignore (non-zero opcode/rcode).
+    Unsupported,
+    /// A record's data does not match its declared type.
+    BadRecordData(u16),
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("truncated mDNS message"),
+            Self::NameTooLong => f.write_str("mDNS name exceeds length limits"),
+            Self::InvalidLabel => f.write_str("invalid mDNS label"),
+            Self::BadPointer => f.write_str("invalid mDNS compression pointer"),
+            Self::Unsupported => f.write_str("unsupported mDNS opcode or rcode"),
+            Self::BadRecordData(rtype) => write!(f, "malformed data for record type {rtype}"),
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+/// A domain name as a list of labels.
+#[derive(Debug, Clone, Default)]
+pub struct Name {
+    labels: Vec<String>,
+}
+
+impl Name {
+    /// Builds a name from individual labels.
+    #[must_use]
+    pub fn from_labels<I, S>(labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            labels: labels.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Parses a dotted name such as `_robot._udp.local`.
+    ///
+    /// Dots always separate labels; use [`Name::prepend`] for labels that
+    /// contain dots.
+    #[must_use]
+    pub fn parse(dotted: &str) -> Self {
+        let trimmed = dotted.strip_suffix('.').unwrap_or(dotted);
+        Self::from_labels(trimmed.split('.').filter(|label| !label.is_empty()))
+    }
+
+    /// Returns a new name with `label` prepended.
+    #[must_use]
+    pub fn prepend(&self, label: impl Into<String>) -> Self {
+        let mut labels = Vec::with_capacity(self.labels.len() + 1);
+        labels.push(label.into());
+        labels.extend(self.labels.iter().cloned());
+        Self { labels }
+    }
+
+    /// Returns the labels of the name.
+    #[must_use]
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// If `self` is exactly one label below `parent`, returns that label.
+    #[must_use]
+    pub fn child_label_of(&self, parent: &Self) -> Option<&str> {
+        let (first, rest) = self.labels.split_first()?;
+        (rest.len() == parent.labels.len()
+            && rest
+                .iter()
+                .zip(&parent.labels)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b)))
+        .then_some(first.as_str())
+    }
+
+    /// Returns the name in lowercase, for use as a map key.
+    #[must_use]
+    pub fn to_key(&self) -> String {
+        self.to_string().to_ascii_lowercase()
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.labels.iter().map(|label| label.len() + 1).sum::<usize>() + 1
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) -> Result<(), WireError> {
+        if self.encoded_len() > MAX_NAME_LEN {
+            return Err(WireError::NameTooLong);
+        }
+        for label in &self.labels {
+            if label.is_empty() {
+                return Err(WireError::InvalidLabel);
+            }
+            if label.len() > MAX_LABEL_LEN {
+                return Err(WireError::NameTooLong);
+            }
+            out.push(label.len() as u8);
+            out.extend_from_slice(label.as_bytes());
+        }
+        out.push(0);
+        Ok(())
+    }
+}
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.labels.len() == other.labels.len()
+            && self
+                .labels
+                .iter()
+                .zip(&other.labels)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+}
+
+impl Eq for Name {}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, label) in self.labels.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            f.write_str(label)?;
+        }
+        Ok(())
+    }
+}
+
+/// A question in an mDNS query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    /// Queried name.
+    pub name: Name,
+    /// Queried record type.
+    pub qtype: u16,
+    /// Whether the querier prefers a unicast response (the QU bit).
+    pub unicast_response: bool,
+}
+
+impl Question {
+    /// Creates a multicast-response question for `name` and `qtype`.
+    #[must_use]
+    pub const fn new(name: Name, qtype: u16) -> Self {
+        Self {
+            name,
+            qtype,
+            unicast_response: false,
+        }
+    }
+}
+
+/// Typed resource record data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData {
+    /// IPv4 address.
+    A(Ipv4Addr),
+    /// IPv6 address.
+    Aaaa(Ipv6Addr),
+    /// Pointer to another name.
+    Ptr(Name),
+    /// Service location.
+    Srv {
+        /// Target priority.
+        priority: u16,
+        /// Relative weight among equal priorities.
+        weight: u16,
+        /// Service port.
+        port: u16,
+        /// Host providing the service.
+        target: Name,
+    },
+    /// Text strings, each at most 255 bytes.
+    Txt(Vec<Vec<u8>>),
+    /// Any other record type, kept opaque.
+    Other {
+        /// Record type.
+        rtype: u16,
+        /// Raw record data.
+        data: Vec<u8>,
+    },
+}
+
+impl RecordData {
+    /// Returns the record type code.
+    #[must_use]
+    pub const fn rtype(&self) -> u16 {
+        match self {
+            Self::A(_) => TYPE_A,
+            Self::Aaaa(_) => TYPE_AAAA,
+            Self::Ptr(_) => TYPE_PTR,
+            Self::Srv { .. } => TYPE_SRV,
+            Self::Txt(_) => TYPE_TXT,
+            Self::Other { rtype, .. } => *rtype,
+        }
+    }
+
+    /// Returns the uncompressed wire form of the data, used for the
+    /// lexicographic comparison of simultaneous probes (RFC 6762 §8.2).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WireError> {
+        let mut out = Vec::new();
+        match self {
+            Self::A(addr) => out.extend_from_slice(&addr.octets()),
+            Self::Aaaa(addr) => out.extend_from_slice(&addr.octets()),
+            Self::Ptr(name) => name.encode(&mut out)?,
+            Self::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                out.extend_from_slice(&priority.to_be_bytes());
+                out.extend_from_slice(&weight.to_be_bytes());
+                out.extend_from_slice(&port.to_be_bytes());
+                target.encode(&mut out)?;
+            }
+            Self::Txt(strings) => {
+                if strings.is_empty() {
+                    out.push(0);
+                }
+                for string in strings {
+                    let len = u8::try_from(string.len()).map_err(|_| WireError::NameTooLong)?;
+                    out.push(len);
+                    out.extend_from_slice(string);
+                }
+            }
+            Self::Other { data, .. } => out.extend_from_slice(data),
+        }
+        Ok(out)
+    }
+}
+
+/// A resource record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Owner name.
+    pub name: Name,
+    /// Whether the cache-flush bit is set (unique records only).
+    pub cache_flush: bool,
+    /// Time to live in seconds; zero announces a goodbye.
+    pub ttl: u32,
+    /// Record data.
+    pub data: RecordData,
+}
+
+impl Record {
+    /// Returns the record type code.
+    #[must_use]
+    pub const fn rtype(&self) -> u16 {
+        self.data.rtype()
+    }
+}
+
+/// An mDNS message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    /// Query id; zero for multicast messages.
+    pub id: u16,
+    /// Whether the message is a response.
+    pub response: bool,
+    /// Question section.
+    pub questions: Vec<Question>,
+    /// Answer section (known answers, in a query).
+    pub answers: Vec<Record>,
+    /// Authority section (proposed records, in a probe).
+    pub authorities: Vec<Record>,
+    /// Additional section.
+    pub additionals: Vec<Record>,
+}
+
+impl Message {
+    /// Creates an empty query.
+    #[must_use]
+    pub fn query() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty response.
+    #[must_use]
+    pub fn response() -> Self {
+        Self {
+            response: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns `true` if the message carries nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+            && self.answers.is_empty()
+            && self.authorities.is_empty()
+            && self.additionals.is_empty()
+    }
+
+    /// Iterates over the answer and additional records.
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.answers.iter().chain(&self.additionals)
+    }
+}
+
+/// Encodes a message without name compression.
+pub fn encode(message: &Message) -> Result<Vec<u8>, WireError> {
+    let mut out = Vec::with_capacity(512);
+    out.extend_from_slice(&message.id.to_be_bytes());
+    let flags = if message.response {
+        FLAG_RESPONSE | FLAG_AUTHORITATIVE
+    } else {
+        0
+    };
+    out.extend_from_slice(&flags.to_be_bytes());
+    for count in [
+        message.questions.len(),
+        message.answers.len(),
+        message.authorities.len(),
+        message.additionals.len(),
+    ] {
+        let count = u16::try_from(count).map_err(|_| WireError::Unsupported)?;
+        out.extend_from_slice(&count.to_be_bytes());
+    }
+    for question in &message.questions {
+        question.name.encode(&mut out)?;
+        out.extend_from_slice(&question.qtype.to_be_bytes());
+        let class = if question.unicast_response {
+            CLASS_IN | UNICAST_RESPONSE_BIT
+        } else {
+            CLASS_IN
+        };
+        out.extend_from_slice(&class.to_be_bytes());
+    }
+    for record in message
+        .answers
+        .iter()
+        .chain(&message.authorities)
+        .chain(&message.additionals)
+    {
+        record.name.encode(&mut out)?;
+        out.extend_from_slice(&record.rtype().to_be_bytes());
+        let class = if record.cache_flush {
+            CLASS_IN | CACHE_FLUSH_BIT
+        } else {
+            CLASS_IN
+        };
+        out.extend_from_slice(&class.to_be_bytes());
+        out.extend_from_slice(&record.ttl.to_be_bytes());
+        let data = record.data.to_bytes()?;
+        let len = u16::try_from(data.len()).map_err(|_| WireError::NameTooLong)?;
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(&data);
+    }
+    Ok(out)
+}
+
+/// Decodes a message.
+///
+/// Records of classes other than IN are skipped; records of unknown types are
+/// kept as [`RecordData::Other`].
+pub fn decode(packet: &[u8]) -> Result<Message, WireError> {
+    if packet.len() < HEADER_LEN {
+        return Err(WireError::Truncated);
+    }
+    let mut offset = 0;
+    let id = read_u16(packet, &mut offset)?;
+    let flags = read_u16(packet, &mut offset)?;
+    if flags & FLAG_OPCODE_RCODE != 0 {
+        return Err(WireError::Unsupported);
+    }
+    let questions = read_u16(packet, &mut offset)?;
+    let answers = read_u16(packet, &mut offset)?;
+    let authorities = read_u16(packet, &mut offset)?;
+    let additionals = read_u16(packet, &mut offset)?;
+
+    let mut message = Message {
+        id,
+        response: flags & FLAG_RESPONSE != 0,
+        ..Message::default()
+    };
+    for _ in 0..questions {
+        let name = decode_name(packet, &mut offset)?;
+        let qtype = read_u16(packet, &mut offset)?;
+        let class = read_u16(packet, &mut offset)?;
+        if class & !UNICAST_RESPONSE_BIT == CLASS_IN {
+            message.questions.push(Question {
+                name,
+                qtype,
+                unicast_response: class & UNICAST_RESPONSE_BIT != 0,
+            });
+        }
+    }
+    for (count, section) in [
+        (answers, &mut message.answers),
+        (authorities, &mut message.authorities),
+        (additionals, &mut message.additionals),
+    ] {
+        for _ in 0..count {
+            if let Some(record) = decode_record(packet, &mut offset)? {
+                section.push(record);
+            }
+        }
+    }
+    Ok(message)
+}
+
+fn decode_record(packet: &[u8], offset: &mut usize) -> Result<Option<Record>, WireError> {
+    let name = decode_name(packet, offset)?;
+    let rtype = read_u16(packet, offset)?;
+    let class = read_u16(packet, offset)?;
+    let ttl = read_u32(packet, offset)?;
+    let len = usize::from(read_u16(packet, offset)?);
+    let start = *offset;
+    let end = start.checked_add(len).ok_or(WireError::Truncated)?;
+    let rdata = packet.get(start..end).ok_or(WireError::Truncated)?;
+    *offset = end;
+    if class & !CACHE_FLUSH_BIT != CLASS_IN {
+        return Ok(None);
+    }
+
+    let bad = || WireError::BadRecordData(rtype);
+    let data = match rtype {
+        TYPE_A => RecordData::A(Ipv4Addr::from(<[u8; 4]>::try_from(rdata).map_err(|_| bad())?)),
+        TYPE_AAAA => {
+            RecordData::Aaaa(Ipv6Addr::from(<[u8; 16]>::try_from(rdata).map_err(|_| bad())?))
+        }
+        TYPE_PTR => {
+            let mut cursor = start;
+            let target = decode_name(packet, &mut cursor)?;
+            if cursor != end {
+                return Err(bad());
+            }
+            RecordData::Ptr(target)
+        }
+        TYPE_SRV => {
+            let mut cursor = start;
+            let priority = read_u16(packet, &mut cursor)?;
+            let weight = read_u16(packet, &mut cursor)?;
+            let port = read_u16(packet, &mut cursor)?;
+            let target = decode_name(packet, &mut cursor)?;
+            if cursor != end {
+                return Err(bad());
+            }
+            RecordData::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            }
+        }
+        TYPE_TXT => {
+            let mut strings = Vec::new();
+            let mut cursor = 0;
+            while cursor < rdata.len() {
+                let len = usize::from(rdata[cursor]);
+                let string = rdata
+                    .get(cursor + 1..cursor + 1 + len)
+                    .ok_or_else(bad)?;
+                if !string.is_empty() {
+                    strings.push(string.to_vec());
+                }
+                cursor += 1 + len;
+            }
+            RecordData::Txt(strings)
+        }
+        _ => RecordData::Other {
+            rtype,
+            data: rdata.to_vec(),
+        },
+    };
+    Ok(Some(Record {
+        name,
+        cache_flush: class & CACHE_FLUSH_BIT != 0,
+        ttl,
+        data,
+    }))
+}
+
+fn read_u16(packet: &[u8], offset: &mut usize) -> Result<u16, WireError> {
+    let bytes = packet
+        .get(*offset..offset.saturating_add(2))
+        .ok_or(WireError::Truncated)?;
+    *offset += 2;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(packet: &[u8], offset: &mut usize) -> Result<u32, WireError> {
+    let bytes = packet
+        .get(*offset..offset.saturating_add(4))
+        .ok_or(WireError::Truncated)?;
+    *offset += 4;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn decode_name(packet: &[u8], offset: &mut usize) -> Result<Name, WireError> {
+    let mut labels = Vec::new();
+    let mut encoded_len = 1;
+    let mut cursor = *offset;
+    let mut resume = None;
+    let mut depth = 0;
+    loop {
+        let len = *packet.get(cursor).ok_or(WireError::Truncated)?;
+        match len & 0xC0 {
+            0xC0 => {
+                let low = *packet.get(cursor + 1).ok_or(WireError::Truncated)?;
+                let pointer = (usize::from(len & 0x3F) << 8) | usize::from(low);
+                // Pointers must go strictly backwards; together with the depth
+                // bound this rules out loops.
+                depth += 1;
+                if pointer >= cursor || depth > MAX_POINTER_DEPTH {
+                    return Err(WireError::BadPointer);
+                }
+                resume.get_or_insert(cursor + 2);
+                cursor = pointer;
+            }
+            0x00 => {
+                cursor += 1;
+                if len == 0 {
+                    break;
+                }
+                let end = cursor + usize::from(len);
+                let label = packet.get(cursor..end).ok_or(WireError::Truncated)?;
+                encoded_len += label.len() + 1;
+                if encoded_len > MAX_NAME_LEN {
+                    return Err(WireError::NameTooLong);
+                }
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                cursor = end;
+            }
+            _ => return Err(WireError::InvalidLabel),
+        }
+    }
+    *offset = resume.unwrap_or(cursor);
+    Ok(Name { labels })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn roundtrip_preserves_sections_and_bits() {
+        init_test("roundtrip_preserves_sections_and_bits");
+        let service = Name::parse("_robot._udp.local");
+        let instance = service.prepend("arm.left (2)");
+        let mut message = Message::response();
+        message.answers.push(Record {
+            name: service,
+            cache_flush: false,
+            ttl: 4500,
+            data: RecordData::Ptr(instance.clone()),
+        });
+        message.additionals.push(Record {
+            name: instance.clone(),
+            cache_flush: true,
+            ttl: 120,
+            data: RecordData::Srv {
+                priority: 0,
+                weight: 0,
+                port: 7000,
+                target: Name::parse("arm.local"),
+            },
+        });
+        message.additionals.push(Record {
+            name: instance,
+            cache_flush: true,
+            ttl: 0,
+            data: RecordData::Txt(vec![b"caps=grip".to_vec(), b"schema=3".to_vec()]),
+        });
+
+        let bytes = encode(&message).expect("encode");
+        let decoded = decode(&bytes).expect("decode");
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.additionals[0].name.labels()[0], "arm.left (2)");
+        crate::test_complete!("roundtrip_preserves_sections_and_bits");
+    }
+
+    #[test]
+    fn names_compare_case_insensitively() {
+        init_test("names_compare_case_insensitively");
+        let service = Name::parse("_Robot._UDP.local.");
+        let instance = Name::parse("_robot._udp.local").prepend("Arm");
+        assert_eq!(service, Name::parse("_robot._udp.local"));
+        assert_eq!(instance.child_label_of(&service), Some("Arm"));
+        assert_eq!(service.child_label_of(&service), None);
+        assert_eq!(service.to_key(), "_robot._udp.local");
+        crate::test_complete!("names_compare_case_insensitively");
+    }
+
+    #[test]
+    fn decodes_compressed_names() {
+        init_test("decodes_compressed_names");
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 2, 0, 0, 0, 0];
+        // local PTR -> a.local (via pointer to offset 12)
+        packet.extend_from_slice(&[5, b'l', b'o', b'c', b'a', b'l', 0]);
+        packet.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 10, 0, 4]);
+        packet.extend_from_slice(&[1, b'a', 0xC0, 12]);
+        // second answer owner is a pointer to the PTR target at offset 29
+        packet.extend_from_slice(&[0xC0, 29, 0, 1, 0x80, 1, 0, 0, 0, 10, 0, 4, 10, 0, 0, 1]);
+
+        let message = decode(&packet).expect("decode");
+        assert_eq!(message.answers.len(), 2);
+        assert_eq!(
+            message.answers[0].data,
+            RecordData::Ptr(Name::parse("a.local"))
+        );
+        assert_eq!(message.answers[1].name, Name::parse("a.local"));
+        assert!(message.answers[1].cache_flush);
+        assert_eq!(message.answers[1].data, RecordData::A(Ipv4Addr::new(10, 0, 0, 1)));
+        crate::test_complete!("decodes_compressed_names");
+    }
+
+    #[test]
+    fn malformed_input_is_rejected_without_panic() {
+        init_test("malformed_input_is_rejected_without_panic");
+        // Self-referential pointer.
+        let mut looped = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        looped.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1]);
+        assert_eq!(decode(&looped), Err(WireError::BadPointer));
+        // Reserved label bits.
+        let mut reserved = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        reserved.extend_from_slice(&[0x40, 0, 0, 1, 0, 1]);
+        assert_eq!(decode(&reserved), Err(WireError::InvalidLabel));
+        // Count larger than the payload.
+        assert_eq!(
+            decode(&[0, 0, 0x84, 0, 0, 0, 0xFF, 0xFF, 0, 0, 0, 0]),
+            Err(WireError::Truncated)
+        );
+        // Non-zero opcode.
+        assert_eq!(
+            decode(&[0, 0, 0x28, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Err(WireError::Unsupported)
+        );
+        // A record with the wrong length.
+        let mut bad_a = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        bad_a.extend_from_slice(&[0, 0, 1, 0, 1, 0, 0, 0, 10, 0, 3, 1, 2, 3]);
+        assert_eq!(decode(&bad_a), Err(WireError::BadRecordData(TYPE_A)));
+
+        // Every truncation of a valid message fails cleanly.
+        let mut message = Message::response();
+        message.answers.push(Record {
+            name: Name::parse("a.local"),
+            cache_flush: true,
+            ttl: 120,
+            data: RecordData::Txt(vec![b"k=v".to_vec()]),
+        });
+        let bytes = encode(&message).expect("encode");
+        for len in 0..bytes.len() {
+            assert!(decode(&bytes[..len]).is_err(), "prefix of {len} bytes decoded");
+        }
+        crate::test_complete!("malformed_input_is_rejected_without_panic");
+    }
+
+    #[test]
+    fn encode_rejects_oversized_labels() {
+        init_test("encode_rejects_oversized_labels");
+        let mut message = Message::query();
+        message
+            .questions
+            .push(Question::new(Name::from_labels(["x".repeat(64), "local".into()]), TYPE_ANY));
+        assert_eq!(encode(&message), Err(WireError::NameTooLong));
+        crate::test_complete!("encode_rejects_oversized_labels");
+    }
+}
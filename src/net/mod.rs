@@ -14,8 +14,15 @@ pub mod atp;
 pub mod atp_udp;
 /// DNS resolution with caching and Happy Eyeballs support.
 pub mod dns;
+/// Client-side endpoint sets with per-request balancing and health tracking.
+pub mod endpoint_set;
+/// File descriptor exhaustion handling for listener accept paths.
+pub mod fd_exhaustion;
 /// Happy Eyeballs v2 (RFC 8305) concurrent dual-stack connection racing.
 pub mod happy_eyeballs;
+/// mDNS/DNS-SD service advertisement and browsing for local peer discovery.
+#[cfg(feature = "mdns")]
+pub mod mdns;
 /// Native QUIC protocol core codecs and types (Tokio-free, runtime-agnostic).
 pub mod quic_core;
 /// Native QUIC transport state machines (TLS, recovery, streams).
@@ -50,7 +57,8 @@ pub mod sys;
 /// but native socket entry points fail fast with `io::ErrorKind::Unsupported`.
 pub mod tcp;
 mod udp;
-/// Unix domain socket networking primitives (includes `UnixListener`, `UnixStream`).
+/// Unix domain socket networking primitives (includes `UnixListener`, `UnixStream`,
+/// `UnixDatagram`).
 #[cfg(unix)]
 pub mod unix;
 /// WebSocket protocol implementation (RFC 6455).
@@ -69,6 +77,11 @@ pub use atp_udp::{
     AtpUdpReceivedPacket, AtpUdpRecvBatch, AtpUdpSocket, AtpUdpSocketConfig, AtpUdpSocketProfile,
     LabAtpUdpSocket, LabUdpEvent,
 };
+pub use endpoint_set::{
+    BalancePolicy, DnsEndpointSource, EjectionConfig, EndpointLease, EndpointMetrics, EndpointSet,
+    EndpointSetConfig,
+};
+pub use fd_exhaustion::{FdExhaustionConfig, FdExhaustionSnapshot, FdUsage};
 pub use happy_eyeballs::{HappyEyeballsConfig, connect as happy_eyeballs_connect};
 #[cfg(all(feature = "quic", not(target_arch = "wasm32")))]
 pub use quic::{
@@ -93,6 +106,10 @@ pub use tcp::socket::TcpSocket;
 pub use tcp::split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
 pub use tcp::stream::TcpStream;
 pub use tcp::stream::TcpStreamBuilder;
+pub use tcp::tap::{
+    PcapngSink, RingSink, TapBudget, TapConfig, TapDirection, TapEvent, TapFilter, TapRecord,
+    TapSink, TruncationReason,
+};
 pub use udp::{
     RecvStream, SendSink, UDP_DEFAULT_GSO_SEGMENT_BYTES, UDP_MAX_GSO_SEGMENTS,
     UDP_MAX_SENDMMSG_BATCH, UDP_RENDEZVOUS_MAX_ATTEMPTS, UDP_RENDEZVOUS_MAX_CANDIDATES,
@@ -109,7 +126,8 @@ pub use udp::{
 pub use unix::{
     Incoming as UnixIncoming, OwnedReadHalf as UnixOwnedReadHalf,
     OwnedWriteHalf as UnixOwnedWriteHalf, ReadHalf as UnixReadHalf,
-    ReuniteError as UnixReuniteError, UnixListener, UnixStream, WriteHalf as UnixWriteHalf,
+    ReuniteError as UnixReuniteError, UCred, UnixDatagram, UnixListener, UnixStream,
+    VirtualUnixStream, WriteHalf as UnixWriteHalf,
 };
 pub use websocket::{
     ClientHandshake, CloseCode, Frame, FrameCodec, HandshakeError, Message, Opcode, Role as WsRole,
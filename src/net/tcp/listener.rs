@@ -4,9 +4,14 @@
 //! The listener implements [`TcpListenerApi`] for use with generic code and frameworks.
 
 use crate::cx::Cx;
+use crate::error::{is_fd_exhaustion, map_fd_exhaustion};
+use crate::net::fd_exhaustion::{
+    AcceptFdGuard, EmergencyFd, FdExhaustionConfig, FdExhaustionSnapshot,
+};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::net::lookup_all;
 use crate::net::tcp::stream::TcpStream;
+use crate::net::tcp::tap::{TapConfig, TapFilter};
 use crate::net::tcp::traits::TcpListenerApi;
 use crate::runtime::io_driver::IoRegistration;
 use crate::runtime::reactor::Interest;
@@ -94,7 +99,10 @@ pub struct TcpListener {
     pub(crate) inner: net::TcpListener,
     accept_storm: Mutex<AcceptStormState>,
     accept_waiters: Arc<AcceptWaiters>,
+    fd_guard: AcceptFdGuard,
     time_getter: fn() -> Time,
+    reactor_affinity: Option<usize>,
+    accept_tap: Mutex<Option<(TapFilter, TapConfig)>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -123,17 +131,92 @@ impl TcpListener {
         // Ensure accept polling never blocks when callers pass a default
         // blocking std listener.
         inner.set_nonblocking(true)?;
+        let fd_guard = AcceptFdGuard::new(FdExhaustionConfig::default(), || {
+            reserve_emergency_fd(&inner)
+        });
         Ok(Self {
             inner,
             registration: Mutex::new(None),
             accept_storm: Mutex::new(AcceptStormState::default()),
             accept_waiters: Arc::new(AcceptWaiters::default()),
+            fd_guard,
             time_getter,
+            reactor_affinity: None,
+            accept_tap: Mutex::new(None),
         })
     }
 
+    /// Replaces the file descriptor exhaustion policy.
+    ///
+    /// By default the listener reserves one emergency descriptor, sheds the
+    /// pending connection with it on `EMFILE`/`ENFILE`, and stops accepting
+    /// for 100ms of runtime time. See [`FdExhaustionConfig`].
+    #[must_use]
+    pub fn with_fd_exhaustion_config(mut self, config: FdExhaustionConfig) -> Self {
+        self.fd_guard = AcceptFdGuard::new(config, || reserve_emergency_fd(&self.inner));
+        self
+    }
+
+    /// Pins the listener, and every stream it accepts, to the reactor at
+    /// index `reactor` on a multi-reactor runtime (`None` restores
+    /// round-robin placement).
+    ///
+    /// The index wraps modulo the runtime's reactor count. Applies to
+    /// registrations made after this call; a listener already registered
+    /// stays on its reactor.
+    #[must_use]
+    pub fn with_reactor_affinity(mut self, reactor: Option<usize>) -> Self {
+        self.reactor_affinity = reactor;
+        self
+    }
+
+    /// Returns the reactor affinity set with
+    /// [`with_reactor_affinity`](Self::with_reactor_affinity).
+    #[must_use]
+    pub fn reactor_affinity(&self) -> Option<usize> {
+        self.reactor_affinity
+    }
+
+    /// Taps every connection accepted from now on whose peer matches
+    /// `filter`, replacing any previous listener tap.
+    ///
+    /// Each accepted connection gets its own connection id and
+    /// [`TapConfig::max_bytes`] allowance; all of them draw from
+    /// `config.budget`. Connections already accepted are unaffected.
+    pub fn tap_accepted(&self, filter: TapFilter, config: TapConfig) {
+        *self.accept_tap.lock() = Some((filter, config));
+    }
+
+    /// Stops tapping newly accepted connections.
+    pub fn clear_accepted_tap(&self) {
+        *self.accept_tap.lock() = None;
+    }
+
+    fn wrap_accepted(&self, stream: net::TcpStream, peer: SocketAddr) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::from_std(stream)?;
+        stream.set_reactor_affinity(self.reactor_affinity);
+        let config = self
+            .accept_tap
+            .lock()
+            .as_ref()
+            .filter(|(filter, _)| filter.matches(peer))
+            .map(|(_, config)| config.clone());
+        if let Some(config) = config {
+            stream.attach_tap(peer, config);
+        }
+        Ok(stream)
+    }
+
+    /// Returns the listener's file descriptor exhaustion counters, including
+    /// the most recent descriptor usage sample.
+    #[must_use]
+    pub fn fd_exhaustion_snapshot(&self) -> FdExhaustionSnapshot {
+        self.fd_guard.snapshot()
+    }
+
     /// Bind to address.
     pub async fn bind<A: ToSocketAddrs + Send + 'static>(addr: A) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         #[cfg(target_arch = "wasm32")]
         {
             let _ = addr;
@@ -175,12 +258,25 @@ impl TcpListener {
         if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
             return Poll::Ready(Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled")));
         }
+        let now = (self.time_getter)();
+        if let Some(deadline) = self.fd_guard.cooldown_deadline(now) {
+            // Inside an fd-exhaustion cooldown: do not touch accept(2) until
+            // the single armed timer wake fires.
+            self.accept_waiters.register(cx.waker());
+            let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+            if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+                return Poll::Pending;
+            }
+            self.accept_waiters.wake_others(cx.waker());
+        }
         match self.inner.accept() {
             Ok((stream, addr)) => {
                 self.reset_accept_storm();
+                self.fd_guard.observe_usage(now);
                 self.accept_waiters.wake_others(cx.waker());
-                Poll::Ready(TcpStream::from_std(stream).map(|stream| (stream, addr)))
+                Poll::Ready(self.wrap_accepted(stream, addr).map(|stream| (stream, addr)))
             }
+            Err(e) if is_fd_exhaustion(&e) => self.poll_fd_exhausted(cx, now, e),
             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
                 self.accept_waiters.register(cx.waker());
                 let storm_backoff = self.note_accept_would_block();
@@ -203,10 +299,13 @@ impl TcpListener {
                         self.reset_accept_storm();
                         self.accept_waiters.wake_others(cx.waker());
                         return Poll::Ready(
-                            TcpStream::from_std(stream).map(|stream| (stream, addr)),
+                            self.wrap_accepted(stream, addr).map(|stream| (stream, addr)),
                         );
                     }
                     Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                    Err(err) if is_fd_exhaustion(&err) => {
+                        return self.poll_fd_exhausted(cx, now, err);
+                    }
                     Err(err) => {
                         self.accept_waiters.wake_others(cx.waker());
                         return Poll::Ready(Err(err));
@@ -233,6 +332,31 @@ impl TcpListener {
         }
     }
 
+    /// Sheds the pending connection with the emergency descriptor and enters
+    /// the accept cooldown. Falls back to returning the typed error when no
+    /// timer driver can end the cooldown.
+    fn poll_fd_exhausted(
+        &self,
+        cx: &mut Context<'_>,
+        now: Time,
+        err: io::Error,
+    ) -> Poll<io::Result<(TcpStream, SocketAddr)>> {
+        let deadline = self.fd_guard.on_exhaustion(
+            now,
+            // The accepted socket is dropped at once, so the client sees a
+            // clean close instead of waiting in the backlog.
+            || self.inner.accept().is_ok(),
+            || reserve_emergency_fd(&self.inner),
+        );
+        self.accept_waiters.register(cx.waker());
+        let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+        if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+            return Poll::Pending;
+        }
+        self.accept_waiters.wake_others(cx.waker());
+        Poll::Ready(Err(map_fd_exhaustion(err)))
+    }
+
     fn note_accept_would_block(&self) -> Duration {
         let mut state = self.accept_storm.lock();
         let now = (self.time_getter)();
@@ -321,7 +445,12 @@ impl TcpListener {
             return Ok(InterestRegistrationMode::FallbackPoll);
         };
 
-        match driver.register(&self.inner, Interest::READABLE, accept_waker) {
+        match driver.register_with_affinity(
+            &self.inner,
+            Interest::READABLE,
+            accept_waker,
+            self.reactor_affinity,
+        ) {
             Ok(new_reg) => {
                 *registration = Some(new_reg);
                 drop(registration);
@@ -338,6 +467,11 @@ impl TcpListener {
     }
 }
 
+/// Reserves the emergency descriptor as a duplicate of the listening socket.
+fn reserve_emergency_fd(inner: &net::TcpListener) -> Option<EmergencyFd> {
+    inner.try_clone().ok().map(EmergencyFd::new)
+}
+
 fn schedule_accept_retry(
     mode: InterestRegistrationMode,
     delay: Duration,
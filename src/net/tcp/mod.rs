@@ -9,6 +9,7 @@ pub mod listener;
 pub mod socket;
 pub mod split;
 pub mod stream;
+pub mod tap;
 pub mod traits;
 pub mod virtual_tcp;
 
@@ -32,6 +32,7 @@ struct TcpSocketState {
     keepalive: KeepaliveConfig,
     #[cfg(unix)]
     reuseport: bool,
+    reactor_affinity: Option<usize>,
 }
 
 impl TcpSocket {
@@ -47,6 +48,7 @@ impl TcpSocket {
                 keepalive: KeepaliveConfig::Default,
                 #[cfg(unix)]
                 reuseport: false,
+                reactor_affinity: None,
             }),
         })
     }
@@ -63,6 +65,7 @@ impl TcpSocket {
                 keepalive: KeepaliveConfig::Default,
                 #[cfg(unix)]
                 reuseport: false,
+                reactor_affinity: None,
             }),
         })
     }
@@ -106,6 +109,17 @@ impl TcpSocket {
         Ok(())
     }
 
+    /// Pins the listener or stream created from this socket to the reactor at
+    /// index `reactor` on a multi-reactor runtime (`None`, the default,
+    /// places it round-robin).
+    ///
+    /// The index wraps modulo the runtime's reactor count. Streams accepted
+    /// by a pinned listener inherit its affinity.
+    pub fn set_reactor_affinity(&self, reactor: Option<usize>) -> io::Result<()> {
+        self.state.lock().reactor_affinity = reactor;
+        Ok(())
+    }
+
     /// Binds this socket to the given local address.
     pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
         {
@@ -129,6 +143,7 @@ impl TcpSocket {
 
     /// Starts listening, returning a TCP listener.
     pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         #[cfg(target_arch = "wasm32")]
         {
             let _ = self;
@@ -165,11 +180,13 @@ impl TcpSocket {
             socket.set_nonblocking(true)?;
 
             TcpListener::from_std(socket.into())
+                .map(|listener| listener.with_reactor_affinity(state.reactor_affinity))
         }
     }
 
     /// Connects this socket, returning a TCP stream.
     pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         #[cfg(target_arch = "wasm32")]
         {
             let _ = self;
@@ -210,7 +227,7 @@ impl TcpSocket {
             }
 
             // Async connect using the configured socket
-            TcpStream::connect_from_socket(socket, addr).await
+            TcpStream::connect_from_socket(socket, addr, state.reactor_affinity).await
         }
     }
 }
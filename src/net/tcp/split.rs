@@ -9,6 +9,8 @@
 #[cfg(not(target_arch = "wasm32"))]
 use crate::cx::Cx;
 use crate::io::{AsyncRead, AsyncReadVectored, AsyncWrite, ReadBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::net::tcp::tap::{ConnectionTap, TapDirection};
 use crate::runtime::io_driver::IoRegistration;
 use crate::runtime::reactor::Interest;
 use parking_lot::Mutex;
@@ -510,6 +512,13 @@ pub(crate) struct TcpStreamInner {
     /// The underlying TCP stream.
     #[cfg(not(target_arch = "wasm32"))]
     stream: Arc<net::TcpStream>,
+    /// Reactor index used for fresh registrations (see
+    /// [`TcpStream::set_reactor_affinity`](super::stream::TcpStream::set_reactor_affinity)).
+    #[cfg(not(target_arch = "wasm32"))]
+    reactor_affinity: Option<usize>,
+    /// Byte tap inherited from the stream, shared by both halves.
+    #[cfg(not(target_arch = "wasm32"))]
+    tap: Option<Arc<ConnectionTap>>,
     #[cfg(target_arch = "wasm32")]
     #[allow(dead_code)]
     unsupported: (),
@@ -575,7 +584,12 @@ impl TcpStreamInner {
             return Ok(installed);
         };
 
-        match driver.register(&*self.stream, desired_interest, waker) {
+        match driver.register_with_affinity(
+            &*self.stream,
+            desired_interest,
+            waker,
+            self.reactor_affinity,
+        ) {
             Ok(registration) => {
                 guard.registration = Some(registration);
                 guard.registration_transition = false;
@@ -701,7 +715,12 @@ impl TcpStreamInner {
 
             // Keep the state lock across fresh registration so concurrent
             // halves cannot both issue reactor ADD for the same socket.
-            match driver.register(&*self.stream, desired_interest, waker) {
+            match driver.register_with_affinity(
+                &*self.stream,
+                desired_interest,
+                waker,
+                self.reactor_affinity,
+            ) {
                 Ok(registration) => {
                     guard.registration = Some(registration);
                     drop(guard);
@@ -824,15 +843,34 @@ pub struct OwnedReadHalf {
 }
 
 impl OwnedReadHalf {
+    /// Create a paired read and write half with no reactor affinity.
+    #[cfg(all(test, not(target_arch = "wasm32")))]
+    pub(crate) fn new_pair(
+        stream: Arc<net::TcpStream>,
+        registration: Option<IoRegistration>,
+    ) -> (Self, OwnedWriteHalf) {
+        Self::new_pair_with_affinity(stream, registration, None, None)
+    }
+
     /// Create a paired read and write half sharing the same inner state.
+    ///
+    /// `reactor_affinity` is kept for registrations the halves make later;
+    /// `tap` captures the traffic of both halves.
     #[cfg(not(target_arch = "wasm32"))]
-    pub(crate) fn new_pair(
+    pub(crate) fn new_pair_with_affinity(
         stream: Arc<net::TcpStream>,
         registration: Option<IoRegistration>,
+        reactor_affinity: Option<usize>,
+        tap: Option<Arc<ConnectionTap>>,
     ) -> (Self, OwnedWriteHalf) {
         let state = Arc::new(Mutex::new(split_io_state(None)));
         adopt_inherited_registration(&state, registration);
-        let inner = Arc::new(TcpStreamInner { state, stream });
+        let inner = Arc::new(TcpStreamInner {
+            state,
+            stream,
+            reactor_affinity,
+            tap,
+        });
         (
             Self {
                 inner: inner.clone(),
@@ -940,6 +978,8 @@ impl OwnedReadHalf {
             Ok(super::stream::TcpStream::from_parts(
                 self.inner.stream.clone(),
                 registration,
+                self.inner.reactor_affinity,
+                self.inner.tap.clone(),
             ))
         } else {
             Err(ReuniteError { read: self, write })
@@ -962,6 +1002,10 @@ impl AsyncRead for OwnedReadHalf {
         match result {
             Ok(n) => {
                 buf.advance(n);
+                if let Some(tap) = &this.inner.tap {
+                    let filled = buf.filled();
+                    tap.capture(TapDirection::Inbound, &filled[filled.len() - n..]);
+                }
                 this.finish_poll(Ok(()))
             }
             Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => this.pending_on_interest(cx),
@@ -995,7 +1039,12 @@ impl AsyncReadVectored for OwnedReadHalf {
         }
         let result = (&*this.inner.stream).read_vectored(bufs);
         match result {
-            Ok(n) => this.finish_poll(Ok(n)),
+            Ok(n) => {
+                if let Some(tap) = &this.inner.tap {
+                    tap.capture_vectored(TapDirection::Inbound, bufs.iter().map(|b| &**b), n);
+                }
+                this.finish_poll(Ok(n))
+            }
             Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => this.pending_on_interest(cx),
             Err(err) => this.finish_poll(Err(err)),
         }
@@ -1092,7 +1141,12 @@ impl AsyncWrite for OwnedWriteHalf {
         }
         let result = (&*this.inner.stream).write(buf);
         match result {
-            Ok(n) => this.finish_poll(Ok(n)),
+            Ok(n) => {
+                if let Some(tap) = &this.inner.tap {
+                    tap.capture(TapDirection::Outbound, &buf[..n]);
+                }
+                this.finish_poll(Ok(n))
+            }
             Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => this.pending_on_interest(cx),
             Err(err) => this.finish_poll(Err(err)),
         }
@@ -1109,7 +1163,12 @@ impl AsyncWrite for OwnedWriteHalf {
         }
         let result = (&*this.inner.stream).write_vectored(bufs);
         match result {
-            Ok(n) => this.finish_poll(Ok(n)),
+            Ok(n) => {
+                if let Some(tap) = &this.inner.tap {
+                    tap.capture_vectored(TapDirection::Outbound, bufs.iter().map(|b| &**b), n);
+                }
+                this.finish_poll(Ok(n))
+            }
             Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => this.pending_on_interest(cx),
             Err(err) => this.finish_poll(Err(err)),
         }
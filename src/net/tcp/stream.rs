@@ -6,10 +6,15 @@
 #![allow(unsafe_code)]
 
 use crate::cx::Cx;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::error::map_fd_exhaustion;
 use crate::io::{AsyncRead, AsyncReadVectored, AsyncWrite, ReadBuf};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::net::lookup_all;
 use crate::net::tcp::split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::net::tcp::tap::TapDirection;
+use crate::net::tcp::tap::{ConnectionTap, TapConfig};
 use crate::net::tcp::traits::TcpStreamApi;
 use crate::runtime::io_driver::IoRegistration;
 use crate::runtime::reactor::Interest;
@@ -53,6 +58,14 @@ pub struct TcpStream {
     inner: Arc<net::TcpStream>,
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     shutdown_on_drop: bool,
+    /// Reactor index used when this stream registers with a multi-reactor
+    /// I/O driver; `None` places it round-robin.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    reactor_affinity: Option<usize>,
+    /// Byte tap attached with [`TcpStream::tap`]; `None` keeps the I/O paths
+    /// copy-free.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    tap: Option<Arc<ConnectionTap>>,
     /// Windows-only retry budget for the transient post-connect `WSAENOTCONN`
     /// (os error 10057). A non-blocking `connect()` is reported complete by
     /// `getpeername` (see `connect_from_socket`), but the socket — notably
@@ -283,6 +296,8 @@ impl TcpStream {
                 inner: Arc::new(stream),
                 registration: None,
                 shutdown_on_drop: true,
+                reactor_affinity: None,
+                tap: None,
                 #[cfg(target_os = "windows")]
                 connect_settle_retries: 0,
             })
@@ -294,11 +309,15 @@ impl TcpStream {
     pub(crate) fn from_parts(
         inner: Arc<net::TcpStream>,
         registration: Option<IoRegistration>,
+        reactor_affinity: Option<usize>,
+        tap: Option<Arc<ConnectionTap>>,
     ) -> Self {
         Self {
             inner,
             registration,
             shutdown_on_drop: true,
+            reactor_affinity,
+            tap,
             #[cfg(target_os = "windows")]
             connect_settle_retries: 0,
         }
@@ -306,6 +325,7 @@ impl TcpStream {
 
     /// Connect to address.
     pub async fn connect<A: ToSocketAddrs + Send + 'static>(addr: A) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         #[cfg(target_arch = "wasm32")]
         {
             let _ = addr;
@@ -320,8 +340,9 @@ impl TcpStream {
                 } else {
                     Domain::IPV6
                 };
-                let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
-                Self::connect_from_socket(socket, addr).await
+                let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))
+                    .map_err(map_fd_exhaustion)?;
+                Self::connect_from_socket(socket, addr, None).await
             })
             .await
         }
@@ -336,8 +357,9 @@ impl TcpStream {
             Domain::IPV6
         };
 
-        let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
-        Self::connect_from_socket(socket, addr).await
+        let socket =
+            Socket::new(domain, Type::STREAM, Some(Protocol::TCP)).map_err(map_fd_exhaustion)?;
+        Self::connect_from_socket(socket, addr, None).await
     }
 
     /// Connect directly to a concrete socket address without DNS resolution.
@@ -350,14 +372,18 @@ impl TcpStream {
 
     /// Connects using an existing configured socket.
     #[cfg(not(target_arch = "wasm32"))]
-    pub(crate) async fn connect_from_socket(socket: Socket, addr: SocketAddr) -> io::Result<Self> {
+    pub(crate) async fn connect_from_socket(
+        socket: Socket,
+        addr: SocketAddr,
+        reactor_affinity: Option<usize>,
+    ) -> io::Result<Self> {
         socket.set_nonblocking(true)?;
 
         // 2. Attempt connect (non-blocking)
         let sock_addr = SockAddr::from(addr);
         let registration = match socket.connect(&sock_addr) {
             Ok(()) => None,
-            Err(err) if connect_in_progress(&err) => wait_for_connect(&socket).await?,
+            Err(err) if connect_in_progress(&err) => wait_for_connect(&socket, reactor_affinity).await?,
             Err(err) => return Err(err),
         };
 
@@ -383,7 +409,7 @@ impl TcpStream {
             match socket.peer_addr() {
                 Ok(_) => None,
                 Err(err) if err.kind() == io::ErrorKind::NotConnected => {
-                    wait_for_connect(&socket).await?
+                    wait_for_connect(&socket, reactor_affinity).await?
                 }
                 Err(err) => return Err(err),
             }
@@ -393,7 +419,7 @@ impl TcpStream {
 
         // socket.into() preserves the nonblocking flag set above; no need to set again.
         let stream: net::TcpStream = socket.into();
-        Ok(Self::from_parts(Arc::new(stream), registration))
+        Ok(Self::from_parts(Arc::new(stream), registration, reactor_affinity, None))
     }
 
     /// Connect with timeout.
@@ -401,6 +427,7 @@ impl TcpStream {
         addr: A,
         timeout_duration: Duration,
     ) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         Self::connect_timeout_with_time_getter(addr, timeout_duration, timeout_now).await
     }
 
@@ -420,8 +447,9 @@ impl TcpStream {
                 } else {
                     Domain::IPV6
                 };
-                let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
-                Self::connect_from_socket(socket, addr).await
+                let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))
+                    .map_err(map_fd_exhaustion)?;
+                Self::connect_from_socket(socket, addr, None).await
             },
         )
         .await
@@ -505,6 +533,24 @@ impl TcpStream {
         }
     }
 
+    /// Pins this stream to the reactor at index `reactor` on a multi-reactor
+    /// runtime; `None` restores round-robin placement.
+    ///
+    /// The index wraps modulo the runtime's reactor count, so every handle of
+    /// one connection group pinned to the same index shares a reactor. Takes
+    /// effect at the next registration: a stream already registered stays on
+    /// its reactor. Split halves keep the affinity.
+    pub fn set_reactor_affinity(&mut self, reactor: Option<usize>) {
+        self.reactor_affinity = reactor;
+    }
+
+    /// Returns the reactor affinity set with
+    /// [`set_reactor_affinity`](Self::set_reactor_affinity).
+    #[must_use]
+    pub fn reactor_affinity(&self) -> Option<usize> {
+        self.reactor_affinity
+    }
+
     /// Set TCP_NODELAY.
     pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
         #[cfg(target_arch = "wasm32")]
@@ -590,10 +636,44 @@ impl TcpStream {
             this.shutdown_on_drop = false;
             let registration = this.registration.take();
             let inner = this.inner.clone();
-            OwnedReadHalf::new_pair(inner, registration)
+            let tap = this.tap.take();
+            OwnedReadHalf::new_pair_with_affinity(inner, registration, this.reactor_affinity, tap)
         }
     }
 
+    /// Attaches a byte tap, replacing any tap already attached.
+    ///
+    /// Captured chunks are tagged with a fresh connection id and this
+    /// stream's peer address. The tap follows the stream through
+    /// [`into_split`](Self::into_split) and [`reunite`](OwnedReadHalf::reunite);
+    /// I/O through the borrowed halves of [`split`](Self::split) is not
+    /// captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer address cannot be determined.
+    pub fn tap(&mut self, config: TapConfig) -> io::Result<()> {
+        let peer = self.peer_addr()?;
+        self.attach_tap(peer, config);
+        Ok(())
+    }
+
+    /// Attaches a byte tap for a connection whose peer is already known.
+    pub(crate) fn attach_tap(&mut self, peer: SocketAddr, config: TapConfig) {
+        self.tap = Some(ConnectionTap::new(peer, self.local_addr().ok(), config));
+    }
+
+    /// Detaches the byte tap, if any.
+    pub fn untap(&mut self) {
+        self.tap = None;
+    }
+
+    /// Returns the connection id used in tap records, if a tap is attached.
+    #[must_use]
+    pub fn tap_connection_id(&self) -> Option<u64> {
+        self.tap.as_ref().map(|tap| tap.connection_id())
+    }
+
     #[cfg(target_arch = "wasm32")]
     #[inline]
     #[allow(dead_code)]
@@ -637,7 +717,12 @@ impl TcpStream {
             return Ok(());
         };
 
-        match driver.register(&*self.inner, target_interest, cx.waker().clone()) {
+        match driver.register_with_affinity(
+            &*self.inner,
+            target_interest,
+            cx.waker().clone(),
+            self.reactor_affinity,
+        ) {
             Ok(registration) => {
                 self.registration = Some(registration);
                 Ok(())
@@ -915,7 +1000,10 @@ fn connect_in_progress(err: &io::Error) -> bool {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-async fn wait_for_connect(socket: &Socket) -> io::Result<Option<IoRegistration>> {
+async fn wait_for_connect(
+    socket: &Socket,
+    reactor_affinity: Option<usize>,
+) -> io::Result<Option<IoRegistration>> {
     let Some(driver) = Cx::current().and_then(|cx| cx.io_driver_handle()) else {
         wait_for_connect_fallback(socket).await?;
         return Ok(None);
@@ -943,7 +1031,12 @@ async fn wait_for_connect(socket: &Socket) -> io::Result<Option<IoRegistration>>
                 }
 
                 if registration.is_none() {
-                    match driver.register(socket, Interest::WRITABLE, cx.waker().clone()) {
+                    match driver.register_with_affinity(
+                        socket,
+                        Interest::WRITABLE,
+                        cx.waker().clone(),
+                        reactor_affinity,
+                    ) {
                         Ok(new_reg) => {
                             registration = Some(new_reg);
                             match poll_connect_complete(socket) {
@@ -1063,6 +1156,10 @@ impl AsyncRead for TcpStream {
         match (&*inner).read(buf.unfilled()) {
             Ok(n) => {
                 buf.advance(n);
+                if let Some(tap) = &this.tap {
+                    let filled = buf.filled();
+                    tap.capture(TapDirection::Inbound, &filled[filled.len() - n..]);
+                }
                 #[cfg(target_os = "windows")]
                 {
                     this.connect_settle_retries = 0;
@@ -1123,6 +1220,9 @@ impl AsyncReadVectored for TcpStream {
         let inner: &net::TcpStream = &this.inner;
         match (&*inner).read_vectored(bufs) {
             Ok(n) => {
+                if let Some(tap) = &this.tap {
+                    tap.capture_vectored(TapDirection::Inbound, bufs.iter().map(|b| &**b), n);
+                }
                 #[cfg(target_os = "windows")]
                 {
                     this.connect_settle_retries = 0;
@@ -1182,6 +1282,9 @@ impl AsyncWrite for TcpStream {
         let inner: &net::TcpStream = &this.inner;
         match (&*inner).write(buf) {
             Ok(n) => {
+                if let Some(tap) = &this.tap {
+                    tap.capture(TapDirection::Outbound, &buf[..n]);
+                }
                 #[cfg(target_os = "windows")]
                 {
                     this.connect_settle_retries = 0;
@@ -1229,6 +1332,9 @@ impl AsyncWrite for TcpStream {
         let inner: &net::TcpStream = &this.inner;
         match (&*inner).write_vectored(bufs) {
             Ok(n) => {
+                if let Some(tap) = &this.tap {
+                    tap.capture_vectored(TapDirection::Outbound, bufs.iter().map(|b| &**b), n);
+                }
                 #[cfg(target_os = "windows")]
                 {
                     this.connect_settle_retries = 0;
@@ -0,0 +1,1207 @@
+//! Opt-in byte taps for debugging TCP connections.
+//!
+//! A tap copies the bytes a connection reads and writes to a [`TapSink`] as
+//! timestamped chunks tagged with a connection id and the peer address. Taps
+//! attach to a [`TcpStream`](super::stream::TcpStream) with
+//! [`TcpStream::tap`](super::stream::TcpStream::tap), to every future
+//! connection of a listener with
+//! [`TcpListener::tap_accepted`](super::listener::TcpListener::tap_accepted),
+//! and to a TLS stream, where they capture the plaintext after decryption.
+//!
+//! # Bounded Capture
+//!
+//! Each tap captures at most [`TapConfig::max_bytes`], and every tap also
+//! draws from a shared [`TapBudget`] (by default [`TapBudget::global`]), so
+//! an accidental tap on a busy listener cannot hold unbounded memory. When
+//! either limit is hit the sink receives one [`TapEvent::Truncated`] record
+//! and the connection stops capturing. Budget is returned when the tapped
+//! connection is dropped.
+//!
+//! # Cost
+//!
+//! An untapped stream carries an empty `Option` and pays one branch per I/O
+//! call; nothing is copied or allocated.
+//!
+//! # Sinks
+//!
+//! - [`RingSink`]: bounded in-memory ring, mainly for tests.
+//! - [`PcapngSink`]: writes a pcapng capture, wrapping the bytes in a
+//!   synthesized TCP flow so Wireshark can dissect the application protocol.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::net::tcp::tap::{PcapngSink, TapConfig, TapDirection};
+//! use std::sync::Arc;
+//!
+//! let sink = Arc::new(PcapngSink::create("/tmp/partner.pcapng")?);
+//! stream.tap(TapConfig::new(TapDirection::Both, 1 << 20, sink.clone()))?;
+//! // ... exchange traffic ...
+//! sink.flush()?;
+//! ```
+
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Which bytes a tap captures, and which way a captured chunk flowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TapDirection {
+    /// Bytes received from the peer.
+    Inbound,
+    /// Bytes sent to the peer.
+    Outbound,
+    /// Both directions. Only used in [`TapConfig`]; records are always
+    /// [`Inbound`](Self::Inbound) or [`Outbound`](Self::Outbound).
+    Both,
+}
+
+impl TapDirection {
+    const fn includes(self, flow: Self) -> bool {
+        matches!(
+            (self, flow),
+            (Self::Both, _) | (Self::Inbound, Self::Inbound) | (Self::Outbound, Self::Outbound)
+        )
+    }
+}
+
+/// Why a tap stopped capturing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TruncationReason {
+    /// The connection reached [`TapConfig::max_bytes`].
+    ConnectionLimit,
+    /// The shared [`TapBudget`] was exhausted.
+    GlobalBudget,
+}
+
+impl fmt::Display for TruncationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConnectionLimit => write!(f, "connection byte limit reached"),
+            Self::GlobalBudget => write!(f, "global tap budget exhausted"),
+        }
+    }
+}
+
+/// Payload of a [`TapRecord`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TapEvent {
+    /// Bytes that crossed the connection.
+    Data(Vec<u8>),
+    /// Capture stopped; later bytes on this connection are not recorded.
+    Truncated(TruncationReason),
+}
+
+/// One captured chunk, delivered to a [`TapSink`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TapRecord {
+    /// Process-unique id of the tapped connection.
+    pub connection_id: u64,
+    /// Remote address of the connection.
+    pub peer: SocketAddr,
+    /// Local address of the connection, when known.
+    pub local: Option<SocketAddr>,
+    /// Runtime time at which the chunk was captured.
+    pub timestamp: Time,
+    /// [`TapDirection::Inbound`] or [`TapDirection::Outbound`].
+    pub direction: TapDirection,
+    /// What was captured.
+    pub event: TapEvent,
+}
+
+/// Destination for captured chunks.
+///
+/// `record` runs inline on the I/O path of the tapped connection, so sinks
+/// should do little more than buffer.
+pub trait TapSink: Send + Sync {
+    /// Receives one captured chunk.
+    fn record(&self, record: TapRecord);
+}
+
+/// Byte budget shared by a set of taps.
+///
+/// Taps reserve budget as they capture and return it when the tapped
+/// connection is dropped.
+#[derive(Debug)]
+pub struct TapBudget {
+    limit: AtomicUsize,
+    used: AtomicUsize,
+}
+
+impl TapBudget {
+    /// Default limit of [`TapBudget::global`]: 64 MiB.
+    pub const DEFAULT_GLOBAL_LIMIT: usize = 64 * 1024 * 1024;
+
+    /// Creates a budget of `limit` bytes.
+    #[must_use]
+    pub const fn new(limit: usize) -> Self {
+        Self {
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// The process-wide budget used by [`TapConfig::new`].
+    #[must_use]
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<TapBudget>> = OnceLock::new();
+        Arc::clone(GLOBAL.get_or_init(|| Arc::new(Self::new(Self::DEFAULT_GLOBAL_LIMIT))))
+    }
+
+    /// Returns the limit in bytes.
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// Changes the limit. Bytes already captured stay reserved.
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// Returns the bytes currently reserved by live taps.
+    #[must_use]
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Reserves up to `wanted` bytes and returns how many were granted.
+    fn reserve(&self, wanted: usize) -> usize {
+        let mut used = self.used.load(Ordering::Relaxed);
+        loop {
+            let granted = wanted.min(self.limit().saturating_sub(used));
+            if granted == 0 {
+                return 0;
+            }
+            match self.used.compare_exchange_weak(
+                used,
+                used + granted,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return granted,
+                Err(current) => used = current,
+            }
+        }
+    }
+
+    fn release(&self, bytes: usize) {
+        self.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Configuration of a tap.
+#[derive(Clone)]
+pub struct TapConfig {
+    /// Which bytes to capture.
+    pub direction: TapDirection,
+    /// Most bytes captured on one connection, both directions combined.
+    pub max_bytes: usize,
+    /// Destination of captured chunks.
+    pub sink: Arc<dyn TapSink>,
+    /// Budget shared with other taps.
+    pub budget: Arc<TapBudget>,
+}
+
+impl TapConfig {
+    /// Creates a configuration drawing from [`TapBudget::global`].
+    #[must_use]
+    pub fn new(direction: TapDirection, max_bytes: usize, sink: Arc<dyn TapSink>) -> Self {
+        Self {
+            direction,
+            max_bytes,
+            sink,
+            budget: TapBudget::global(),
+        }
+    }
+
+    /// Draws from `budget` instead of the global budget.
+    #[must_use]
+    pub fn with_budget(mut self, budget: Arc<TapBudget>) -> Self {
+        self.budget = budget;
+        self
+    }
+}
+
+impl fmt::Debug for TapConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TapConfig")
+            .field("direction", &self.direction)
+            .field("max_bytes", &self.max_bytes)
+            .field("budget", &self.budget)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Peer-address filter for listener taps; see
+/// [`TcpListener::tap_accepted`](super::listener::TcpListener::tap_accepted).
+#[derive(Clone)]
+pub struct TapFilter {
+    matches: Arc<dyn Fn(SocketAddr) -> bool + Send + Sync>,
+}
+
+impl TapFilter {
+    /// Matches every peer.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::new(|_| true)
+    }
+
+    /// Matches peers with address `ip`, on any port.
+    #[must_use]
+    pub fn ip(ip: IpAddr) -> Self {
+        Self::new(move |peer| peer.ip() == ip)
+    }
+
+    /// Matches peers inside `network/prefix_len`.
+    #[must_use]
+    pub fn subnet(network: IpAddr, prefix_len: u8) -> Self {
+        Self::new(move |peer| match (network, peer.ip()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), prefix_len)
+            }
+            _ => false,
+        })
+    }
+
+    /// Matches peers accepted by `predicate`.
+    #[must_use]
+    pub fn new(predicate: impl Fn(SocketAddr) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            matches: Arc::new(predicate),
+        }
+    }
+
+    /// Returns `true` when `peer` should be tapped.
+    #[must_use]
+    pub fn matches(&self, peer: SocketAddr) -> bool {
+        (self.matches)(peer)
+    }
+}
+
+impl fmt::Debug for TapFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TapFilter").finish_non_exhaustive()
+    }
+}
+
+fn prefix_matches(network: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+    let bits = usize::from(prefix_len).min(network.len() * 8);
+    let (whole, rest) = (bits / 8, bits % 8);
+    if network[..whole] != ip[..whole] {
+        return false;
+    }
+    if rest == 0 {
+        return true;
+    }
+    let mask = 0xFF_u8 << (8 - rest);
+    network[whole] & mask == ip[whole] & mask
+}
+
+/// Capture state of one tapped connection, shared by its split halves.
+pub(crate) struct ConnectionTap {
+    connection_id: u64,
+    peer: SocketAddr,
+    local: Option<SocketAddr>,
+    config: TapConfig,
+    state: Mutex<ConnectionTapState>,
+}
+
+#[derive(Default)]
+struct ConnectionTapState {
+    captured: usize,
+    truncated: bool,
+}
+
+impl ConnectionTap {
+    pub(crate) fn new(peer: SocketAddr, local: Option<SocketAddr>, config: TapConfig) -> Arc<Self> {
+        Arc::new(Self {
+            connection_id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            peer,
+            local,
+            config,
+            state: Mutex::new(ConnectionTapState::default()),
+        })
+    }
+
+    pub(crate) const fn connection_id(&self) -> u64 {
+        self.connection_id
+    }
+
+    /// Captures `bytes` that flowed in `direction`.
+    pub(crate) fn capture(&self, direction: TapDirection, bytes: &[u8]) {
+        if bytes.is_empty() || !self.config.direction.includes(direction) {
+            return;
+        }
+        let (granted, truncation) = {
+            let mut state = self.state.lock();
+            if state.truncated {
+                return;
+            }
+            let wanted = bytes.len().min(self.config.max_bytes - state.captured);
+            let granted = self.config.budget.reserve(wanted);
+            state.captured += granted;
+            let truncation = if granted < wanted {
+                Some(TruncationReason::GlobalBudget)
+            } else if granted < bytes.len() {
+                Some(TruncationReason::ConnectionLimit)
+            } else {
+                None
+            };
+            state.truncated = truncation.is_some();
+            drop(state);
+            (granted, truncation)
+        };
+
+        let timestamp = crate::time::wall_now();
+        if granted > 0 {
+            self.emit(timestamp, direction, TapEvent::Data(bytes[..granted].to_vec()));
+        }
+        if let Some(reason) = truncation {
+            self.emit(timestamp, direction, TapEvent::Truncated(reason));
+        }
+    }
+
+    /// Captures the first `len` bytes spread across `slices`.
+    pub(crate) fn capture_vectored<'a>(
+        &self,
+        direction: TapDirection,
+        slices: impl IntoIterator<Item = &'a [u8]>,
+        len: usize,
+    ) {
+        if len == 0 || !self.config.direction.includes(direction) {
+            return;
+        }
+        let mut gathered = Vec::with_capacity(len);
+        for slice in slices {
+            let take = slice.len().min(len - gathered.len());
+            gathered.extend_from_slice(&slice[..take]);
+            if gathered.len() == len {
+                break;
+            }
+        }
+        self.capture(direction, &gathered);
+    }
+
+    fn emit(&self, timestamp: Time, direction: TapDirection, event: TapEvent) {
+        self.config.sink.record(TapRecord {
+            connection_id: self.connection_id,
+            peer: self.peer,
+            local: self.local,
+            timestamp,
+            direction,
+            event,
+        });
+    }
+}
+
+impl Drop for ConnectionTap {
+    fn drop(&mut self) {
+        self.config.budget.release(self.state.get_mut().captured);
+    }
+}
+
+impl fmt::Debug for ConnectionTap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionTap")
+            .field("connection_id", &self.connection_id)
+            .field("peer", &self.peer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// In-memory sink keeping the most recent `capacity` records.
+#[derive(Debug)]
+pub struct RingSink {
+    capacity: usize,
+    records: Mutex<VecDeque<TapRecord>>,
+    evicted: AtomicU64,
+}
+
+impl RingSink {
+    /// Creates a ring holding at most `capacity` records.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: Mutex::new(VecDeque::new()),
+            evicted: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the retained records, oldest first.
+    #[must_use]
+    pub fn records(&self) -> Vec<TapRecord> {
+        self.records.lock().iter().cloned().collect()
+    }
+
+    /// Returns the retained bytes of one connection and direction, in order.
+    #[must_use]
+    pub fn bytes(&self, connection_id: u64, direction: TapDirection) -> Vec<u8> {
+        let records = self.records.lock();
+        let mut bytes = Vec::new();
+        for record in records.iter() {
+            if record.connection_id == connection_id
+                && record.direction == direction
+                && let TapEvent::Data(data) = &record.event
+            {
+                bytes.extend_from_slice(data);
+            }
+        }
+        drop(records);
+        bytes
+    }
+
+    /// Returns how many records were dropped to stay within capacity.
+    #[must_use]
+    pub fn evicted(&self) -> u64 {
+        self.evicted.load(Ordering::Relaxed)
+    }
+}
+
+impl TapSink for RingSink {
+    fn record(&self, record: TapRecord) {
+        let mut records = self.records.lock();
+        if records.len() == self.capacity {
+            records.pop_front();
+            self.evicted.fetch_add(1, Ordering::Relaxed);
+        }
+        records.push_back(record);
+    }
+}
+
+/// pcapng link type for raw IPv4/IPv6 packets.
+const LINKTYPE_RAW: u16 = 101;
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const OPTION_COMMENT: u16 = 1;
+/// Largest payload per synthesized segment; keeps IPv4 and IPv6 lengths in
+/// range.
+const MAX_SEGMENT: usize = 65_000;
+const TCP_SYN: u8 = 0x02;
+const TCP_PSH: u8 = 0x08;
+const TCP_ACK: u8 = 0x10;
+
+/// Sink writing a pcapng capture.
+///
+/// Every tapped connection becomes a synthesized TCP flow between its local
+/// and peer addresses: a three-way handshake on its first record, then one
+/// segment per captured chunk with consistent sequence numbers. Truncation
+/// markers are empty segments carrying a packet comment. Timestamps have
+/// microsecond resolution.
+///
+/// Write errors stop the capture; the first one is reported by
+/// [`flush`](Self::flush).
+pub struct PcapngSink {
+    state: Mutex<PcapngState>,
+}
+
+struct PcapngState {
+    writer: Box<dyn Write + Send>,
+    flows: HashMap<u64, Flow>,
+    next_ip_id: u16,
+    error: Option<io::Error>,
+}
+
+struct Flow {
+    local: SocketAddr,
+    peer: SocketAddr,
+    local_seq: u32,
+    peer_seq: u32,
+}
+
+impl PcapngSink {
+    /// Creates a sink writing to a new file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Creates a sink writing to `writer`, starting with the section header
+    /// and interface description blocks.
+    pub fn new(writer: impl Write + Send + 'static) -> io::Result<Self> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        let mut section = Vec::with_capacity(16);
+        section.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        section.extend_from_slice(&1_u16.to_le_bytes());
+        section.extend_from_slice(&0_u16.to_le_bytes());
+        section.extend_from_slice(&(-1_i64).to_le_bytes());
+        write_block(&mut writer, BLOCK_SECTION_HEADER, &section)?;
+
+        let mut interface = Vec::with_capacity(8);
+        interface.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        interface.extend_from_slice(&0_u16.to_le_bytes());
+        interface.extend_from_slice(&0_u32.to_le_bytes());
+        write_block(&mut writer, BLOCK_INTERFACE_DESCRIPTION, &interface)?;
+
+        Ok(Self {
+            state: Mutex::new(PcapngState {
+                writer,
+                flows: HashMap::new(),
+                next_ip_id: 0,
+                error: None,
+            }),
+        })
+    }
+
+    /// Flushes buffered output, reporting the first write error if any.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut state = self.state.lock();
+        if let Some(error) = state.error.take() {
+            return Err(error);
+        }
+        state.writer.flush()
+    }
+}
+
+impl fmt::Debug for PcapngSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("PcapngSink")
+            .field("flows", &state.flows.len())
+            .field("failed", &state.error.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TapSink for PcapngSink {
+    fn record(&self, record: TapRecord) {
+        let mut state = self.state.lock();
+        if state.error.is_none()
+            && let Err(error) = state.write_record(&record)
+        {
+            state.error = Some(error);
+        }
+    }
+}
+
+impl PcapngState {
+    fn write_record(&mut self, record: &TapRecord) -> io::Result<()> {
+        if !self.flows.contains_key(&record.connection_id) {
+            let flow = self.open_flow(record)?;
+            self.flows.insert(record.connection_id, flow);
+        }
+        let flow = self
+            .flows
+            .get_mut(&record.connection_id)
+            .expect("flow opened above");
+        let outbound = record.direction == TapDirection::Outbound;
+        let (src, dst) = if outbound {
+            (flow.local, flow.peer)
+        } else {
+            (flow.peer, flow.local)
+        };
+
+        let mut packets = Vec::new();
+        match &record.event {
+            TapEvent::Data(data) => {
+                for chunk in data.chunks(MAX_SEGMENT) {
+                    let (seq, ack) = if outbound {
+                        (flow.local_seq, flow.peer_seq)
+                    } else {
+                        (flow.peer_seq, flow.local_seq)
+                    };
+                    packets.push((
+                        TcpSegment {
+                            src,
+                            dst,
+                            seq,
+                            ack,
+                            flags: TCP_PSH | TCP_ACK,
+                        },
+                        chunk,
+                        None,
+                    ));
+                    // Sequence numbers wrap modulo 2^32 by design.
+                    #[allow(clippy::cast_possible_truncation)]
+                    let advance = chunk.len() as u32;
+                    if outbound {
+                        flow.local_seq = flow.local_seq.wrapping_add(advance);
+                    } else {
+                        flow.peer_seq = flow.peer_seq.wrapping_add(advance);
+                    }
+                }
+            }
+            TapEvent::Truncated(reason) => {
+                let (seq, ack) = if outbound {
+                    (flow.local_seq, flow.peer_seq)
+                } else {
+                    (flow.peer_seq, flow.local_seq)
+                };
+                packets.push((
+                    TcpSegment {
+                        src,
+                        dst,
+                        seq,
+                        ack,
+                        flags: TCP_ACK,
+                    },
+                    &[][..],
+                    Some(format!("tap truncated: {reason}")),
+                ));
+            }
+        }
+        for (segment, payload, comment) in packets {
+            self.write_packet(record.timestamp, &segment, payload, comment.as_deref())?;
+        }
+        Ok(())
+    }
+
+    /// Synthesizes a handshake for a new connection. The tapped side is
+    /// shown as the initiator; both initial sequence numbers are zero.
+    fn open_flow(&mut self, record: &TapRecord) -> io::Result<Flow> {
+        let peer = record.peer;
+        let local = record
+            .local
+            .filter(|local| local.is_ipv4() == peer.is_ipv4())
+            .unwrap_or_else(|| {
+                let ip = if peer.is_ipv4() {
+                    IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                } else {
+                    IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+                };
+                SocketAddr::new(ip, 0)
+            });
+        let handshake = [
+            (local, peer, 0, 0, TCP_SYN),
+            (peer, local, 0, 1, TCP_SYN | TCP_ACK),
+            (local, peer, 1, 1, TCP_ACK),
+        ];
+        for (src, dst, seq, ack, flags) in handshake {
+            let segment = TcpSegment {
+                src,
+                dst,
+                seq,
+                ack,
+                flags,
+            };
+            self.write_packet(record.timestamp, &segment, &[], None)?;
+        }
+        Ok(Flow {
+            local,
+            peer,
+            local_seq: 1,
+            peer_seq: 1,
+        })
+    }
+
+    fn write_packet(
+        &mut self,
+        timestamp: Time,
+        segment: &TcpSegment,
+        payload: &[u8],
+        comment: Option<&str>,
+    ) -> io::Result<()> {
+        let ip_id = self.next_ip_id;
+        self.next_ip_id = self.next_ip_id.wrapping_add(1);
+        let packet = segment.to_packet(payload, ip_id);
+        let packet_len = u32::try_from(packet.len()).expect("segment fits in u32");
+        let micros = timestamp.as_nanos() / 1_000;
+
+        // The timestamp is stored as high and low 32-bit words.
+        #[allow(clippy::cast_possible_truncation)]
+        let (high, low) = ((micros >> 32) as u32, micros as u32);
+
+        let mut body = Vec::with_capacity(packet.len() + 64);
+        body.extend_from_slice(&0_u32.to_le_bytes());
+        body.extend_from_slice(&high.to_le_bytes());
+        body.extend_from_slice(&low.to_le_bytes());
+        body.extend_from_slice(&packet_len.to_le_bytes());
+        body.extend_from_slice(&packet_len.to_le_bytes());
+        body.extend_from_slice(&packet);
+        pad_to_word(&mut body);
+        if let Some(comment) = comment {
+            let len = u16::try_from(comment.len()).unwrap_or(u16::MAX);
+            body.extend_from_slice(&OPTION_COMMENT.to_le_bytes());
+            body.extend_from_slice(&len.to_le_bytes());
+            body.extend_from_slice(&comment.as_bytes()[..usize::from(len)]);
+            pad_to_word(&mut body);
+            body.extend_from_slice(&[0; 4]);
+        }
+        write_block(&mut self.writer, BLOCK_ENHANCED_PACKET, &body)
+    }
+}
+
+struct TcpSegment {
+    src: SocketAddr,
+    dst: SocketAddr,
+    seq: u32,
+    ack: u32,
+    flags: u8,
+}
+
+impl TcpSegment {
+    /// Builds the raw IP packet carrying this segment and `payload`.
+    fn to_packet(&self, payload: &[u8], ip_id: u16) -> Vec<u8> {
+        let tcp_len = 20 + payload.len();
+        let mut tcp = Vec::with_capacity(tcp_len);
+        tcp.extend_from_slice(&self.src.port().to_be_bytes());
+        tcp.extend_from_slice(&self.dst.port().to_be_bytes());
+        tcp.extend_from_slice(&self.seq.to_be_bytes());
+        let ack = if self.flags & TCP_ACK == 0 { 0 } else { self.ack };
+        tcp.extend_from_slice(&ack.to_be_bytes());
+        tcp.push(5 << 4);
+        tcp.push(self.flags);
+        tcp.extend_from_slice(&u16::MAX.to_be_bytes());
+        tcp.extend_from_slice(&[0; 4]);
+        tcp.extend_from_slice(payload);
+
+        let tcp_len_u16 = u16::try_from(tcp_len).expect("segment bounded by MAX_SEGMENT");
+        match (self.src.ip(), self.dst.ip()) {
+            (IpAddr::V4(src), IpAddr::V4(dst)) => {
+                let mut pseudo = Vec::with_capacity(12);
+                pseudo.extend_from_slice(&src.octets());
+                pseudo.extend_from_slice(&dst.octets());
+                pseudo.extend_from_slice(&[0, 6]);
+                pseudo.extend_from_slice(&tcp_len_u16.to_be_bytes());
+                let checksum = internet_checksum(&[&pseudo, &tcp]);
+                tcp[16..18].copy_from_slice(&checksum.to_be_bytes());
+
+                let mut packet = Vec::with_capacity(20 + tcp_len);
+                packet.extend_from_slice(&[0x45, 0]);
+                packet.extend_from_slice(&(tcp_len_u16 + 20).to_be_bytes());
+                packet.extend_from_slice(&ip_id.to_be_bytes());
+                packet.extend_from_slice(&0x4000_u16.to_be_bytes());
+                packet.extend_from_slice(&[64, 6, 0, 0]);
+                packet.extend_from_slice(&src.octets());
+                packet.extend_from_slice(&dst.octets());
+                let checksum = internet_checksum(&[&packet]);
+                packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+                packet.extend_from_slice(&tcp);
+                packet
+            }
+            (src, dst) => {
+                let src = to_ipv6(src);
+                let dst = to_ipv6(dst);
+                let mut pseudo = Vec::with_capacity(40);
+                pseudo.extend_from_slice(&src.octets());
+                pseudo.extend_from_slice(&dst.octets());
+                pseudo.extend_from_slice(&u32::from(tcp_len_u16).to_be_bytes());
+                pseudo.extend_from_slice(&[0, 0, 0, 6]);
+                let checksum = internet_checksum(&[&pseudo, &tcp]);
+                tcp[16..18].copy_from_slice(&checksum.to_be_bytes());
+
+                let mut packet = Vec::with_capacity(40 + tcp_len);
+                packet.extend_from_slice(&0x6000_0000_u32.to_be_bytes());
+                packet.extend_from_slice(&tcp_len_u16.to_be_bytes());
+                packet.extend_from_slice(&[6, 64]);
+                packet.extend_from_slice(&src.octets());
+                packet.extend_from_slice(&dst.octets());
+                packet.extend_from_slice(&tcp);
+                packet
+            }
+        }
+    }
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+/// RFC 1071 checksum over the concatenation of `parts` (each even-length
+/// except possibly the last).
+fn internet_checksum(parts: &[&[u8]]) -> u16 {
+    let mut sum = 0_u32;
+    for part in parts {
+        let mut words = part.chunks_exact(2);
+        for word in &mut words {
+            sum += u32::from(u16::from_be_bytes([word[0], word[1]]));
+        }
+        if let [last] = words.remainder() {
+            sum += u32::from(*last) << 8;
+        }
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let folded = sum as u16;
+    !folded
+}
+
+fn pad_to_word(bytes: &mut Vec<u8>) {
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+}
+
+fn write_block(writer: &mut dyn Write, block_type: u32, body: &[u8]) -> io::Result<()> {
+    let padded = body.len().next_multiple_of(4);
+    let total = u32::try_from(padded + 12)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "pcapng block too large"))?;
+    writer.write_all(&block_type.to_le_bytes())?;
+    writer.write_all(&total.to_le_bytes())?;
+    writer.write_all(body)?;
+    writer.write_all(&[0; 4][..padded - body.len()])?;
+    writer.write_all(&total.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::io::{AsyncReadExt, AsyncWriteExt};
+    use crate::net::tcp::listener::TcpListener;
+    use crate::net::tcp::stream::TcpStream;
+    use futures_lite::future;
+    use std::io::Read;
+    use std::net;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// A tapped async client connected to a blocking std server socket.
+    fn tapped_pair(config: TapConfig) -> (TcpStream, net::TcpStream) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let client =
+            net::TcpStream::connect(listener.local_addr().expect("addr")).expect("connect");
+        let (server, _) = listener.accept().expect("accept");
+        let mut client = TcpStream::from_std(client).expect("wrap client");
+        client.tap(config).expect("attach tap");
+        (client, server)
+    }
+
+    fn ring_config(ring: &Arc<RingSink>, max_bytes: usize) -> TapConfig {
+        TapConfig::new(TapDirection::Both, max_bytes, ring.clone())
+            .with_budget(Arc::new(TapBudget::new(usize::MAX)))
+    }
+
+    #[test]
+    fn tap_captures_exchanged_bytes() {
+        init_test("tap_captures_exchanged_bytes");
+        let ring = Arc::new(RingSink::new(64));
+        let (mut client, mut server) = tapped_pair(ring_config(&ring, 1024));
+        let id = client.tap_connection_id().expect("tapped");
+
+        future::block_on(client.write_all(b"GET /status HTTP/1.1\r\n\r\n")).expect("write");
+        let mut request = [0_u8; 24];
+        server.read_exact(&mut request).expect("server read");
+        server.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").expect("server write");
+        let mut response = [0_u8; 27];
+        future::block_on(client.read_exact(&mut response)).expect("read");
+
+        assert_eq!(ring.bytes(id, TapDirection::Outbound), request.to_vec());
+        assert_eq!(ring.bytes(id, TapDirection::Inbound), response.to_vec());
+        let peer = server.local_addr().expect("server addr");
+        for record in ring.records() {
+            assert_eq!(record.connection_id, id);
+            assert_eq!(record.peer, peer);
+            assert_eq!(record.local, Some(server.peer_addr().expect("client addr")));
+        }
+        crate::test_complete!("tap_captures_exchanged_bytes");
+    }
+
+    #[test]
+    fn tap_direction_filters_chunks() {
+        init_test("tap_direction_filters_chunks");
+        let ring = Arc::new(RingSink::new(64));
+        let mut config = ring_config(&ring, 1024);
+        config.direction = TapDirection::Inbound;
+        let (mut client, mut server) = tapped_pair(config);
+
+        future::block_on(client.write_all(b"ping")).expect("write");
+        let mut ping = [0_u8; 4];
+        server.read_exact(&mut ping).expect("server read");
+        server.write_all(b"pong").expect("server write");
+        let mut pong = [0_u8; 4];
+        future::block_on(client.read_exact(&mut pong)).expect("read");
+
+        let records = ring.records();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|record| record.direction == TapDirection::Inbound));
+        crate::test_complete!("tap_direction_filters_chunks");
+    }
+
+    #[test]
+    fn connection_limit_truncates_with_marker() {
+        init_test("connection_limit_truncates_with_marker");
+        let ring = Arc::new(RingSink::new(64));
+        let (mut client, mut server) = tapped_pair(ring_config(&ring, 8));
+
+        future::block_on(client.write_all(b"0123456789abcdef")).expect("write");
+        future::block_on(client.write_all(b"more")).expect("write after truncation");
+        let mut sent = [0_u8; 20];
+        server.read_exact(&mut sent).expect("server read");
+
+        let events: Vec<_> = ring.records().into_iter().map(|record| record.event).collect();
+        assert_eq!(
+            events,
+            vec![
+                TapEvent::Data(b"01234567".to_vec()),
+                TapEvent::Truncated(TruncationReason::ConnectionLimit),
+            ]
+        );
+        crate::test_complete!("connection_limit_truncates_with_marker");
+    }
+
+    #[test]
+    fn shared_budget_truncates_and_is_returned_on_drop() {
+        init_test("shared_budget_truncates_and_is_returned_on_drop");
+        let ring = Arc::new(RingSink::new(64));
+        let budget = Arc::new(TapBudget::new(10));
+        let config = TapConfig::new(TapDirection::Both, 1024, ring.clone())
+            .with_budget(Arc::clone(&budget));
+        let (mut first, _first_server) = tapped_pair(config.clone());
+        let (mut second, _second_server) = tapped_pair(config);
+
+        future::block_on(first.write_all(b"abcdef")).expect("write first");
+        future::block_on(second.write_all(b"ghijkl")).expect("write second");
+        assert_eq!(budget.used(), 10);
+        let second_id = second.tap_connection_id().expect("tapped");
+        let second_events: Vec<_> = ring
+            .records()
+            .into_iter()
+            .filter(|record| record.connection_id == second_id)
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(
+            second_events,
+            vec![
+                TapEvent::Data(b"ghij".to_vec()),
+                TapEvent::Truncated(TruncationReason::GlobalBudget),
+            ]
+        );
+
+        drop(first);
+        drop(second);
+        assert_eq!(budget.used(), 0);
+        crate::test_complete!("shared_budget_truncates_and_is_returned_on_drop");
+    }
+
+    #[test]
+    fn split_halves_share_the_tap() {
+        init_test("split_halves_share_the_tap");
+        let ring = Arc::new(RingSink::new(64));
+        let (client, mut server) = tapped_pair(ring_config(&ring, 1024));
+        let id = client.tap_connection_id().expect("tapped");
+        let (mut read, mut write) = client.into_split();
+
+        future::block_on(write.write_all(b"split")).expect("write");
+        let mut sent = [0_u8; 5];
+        server.read_exact(&mut sent).expect("server read");
+        server.write_all(b"halves").expect("server write");
+        let mut received = [0_u8; 6];
+        future::block_on(read.read_exact(&mut received)).expect("read");
+
+        let reunited = read.reunite(write).expect("reunite");
+        assert_eq!(reunited.tap_connection_id(), Some(id));
+        assert_eq!(ring.bytes(id, TapDirection::Outbound), b"split");
+        assert_eq!(ring.bytes(id, TapDirection::Inbound), b"halves");
+        crate::test_complete!("split_halves_share_the_tap");
+    }
+
+    #[test]
+    fn listener_taps_matching_peers_only() {
+        init_test("listener_taps_matching_peers_only");
+        let ring = Arc::new(RingSink::new(64));
+        let std_listener = net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = std_listener.local_addr().expect("addr");
+        let listener = TcpListener::from_std(std_listener).expect("wrap listener");
+
+        listener.tap_accepted(
+            TapFilter::subnet(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8),
+            ring_config(&ring, 1024),
+        );
+        let _unmatched_client = net::TcpStream::connect(addr).expect("connect");
+        let (unmatched, _) = future::block_on(listener.accept()).expect("accept");
+        assert_eq!(unmatched.tap_connection_id(), None);
+
+        listener.tap_accepted(
+            TapFilter::subnet(IpAddr::V4(Ipv4Addr::LOCALHOST), 8),
+            ring_config(&ring, 1024),
+        );
+        let mut client = net::TcpStream::connect(addr).expect("connect");
+        let (mut accepted, peer) = future::block_on(listener.accept()).expect("accept");
+        let id = accepted.tap_connection_id().expect("matching peer is tapped");
+
+        client.write_all(b"hello").expect("client write");
+        let mut hello = [0_u8; 5];
+        future::block_on(accepted.read_exact(&mut hello)).expect("read");
+        let records = ring.records();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|record| record.peer == peer));
+        assert_eq!(ring.bytes(id, TapDirection::Inbound), b"hello");
+
+        listener.clear_accepted_tap();
+        let _late_client = net::TcpStream::connect(addr).expect("connect");
+        let (late, _) = future::block_on(listener.accept()).expect("accept");
+        assert_eq!(late.tap_connection_id(), None);
+        crate::test_complete!("listener_taps_matching_peers_only");
+    }
+
+    #[test]
+    fn subnet_filter_matches_prefixes() {
+        init_test("subnet_filter_matches_prefixes");
+        let filter = TapFilter::subnet("192.168.4.0".parse().expect("ip"), 22);
+        assert!(filter.matches("192.168.7.255:1".parse().expect("addr")));
+        assert!(!filter.matches("192.168.8.1:1".parse().expect("addr")));
+        assert!(!filter.matches("[::1]:1".parse().expect("addr")));
+        let v6 = TapFilter::subnet("fd00::".parse().expect("ip"), 8);
+        assert!(v6.matches("[fd12::1]:443".parse().expect("addr")));
+        assert!(TapFilter::all().matches("[::1]:1".parse().expect("addr")));
+        crate::test_complete!("subnet_filter_matches_prefixes");
+    }
+
+    /// A flow reconstructed from a pcapng capture by [`check_pcapng`].
+    #[derive(Debug, Default)]
+    struct CheckedCapture {
+        packets: usize,
+        syns: usize,
+        comments: Vec<String>,
+        /// Payload bytes keyed by source port, in capture order.
+        payloads: HashMap<u16, Vec<u8>>,
+    }
+
+    fn u16_le(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().expect("u16"))
+    }
+
+    fn u32_le(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().expect("u32"))
+    }
+
+    /// Minimal pcapng validity checker: block framing, the raw-IP interface,
+    /// IPv4 and TCP checksums, and length consistency of every packet.
+    fn check_pcapng(bytes: &[u8]) -> CheckedCapture {
+        let mut capture = CheckedCapture::default();
+        let mut offset = 0;
+        let mut saw_interface = false;
+        while offset < bytes.len() {
+            let block_type = u32_le(bytes, offset);
+            let total = u32_le(bytes, offset + 4) as usize;
+            assert!(total >= 12 && total % 4 == 0, "bad block length {total}");
+            assert_eq!(u32_le(bytes, offset + total - 4) as usize, total, "trailer");
+            let body = &bytes[offset + 8..offset + total - 4];
+            match block_type {
+                BLOCK_SECTION_HEADER => {
+                    assert_eq!(offset, 0, "section header first");
+                    assert_eq!(u32_le(body, 0), BYTE_ORDER_MAGIC);
+                    assert_eq!(u16_le(body, 4), 1);
+                }
+                BLOCK_INTERFACE_DESCRIPTION => {
+                    assert_eq!(u16_le(body, 0), LINKTYPE_RAW);
+                    saw_interface = true;
+                }
+                BLOCK_ENHANCED_PACKET => {
+                    assert!(saw_interface, "packet before interface");
+                    assert_eq!(u32_le(body, 0), 0, "interface id");
+                    let captured = u32_le(body, 12) as usize;
+                    assert_eq!(u32_le(body, 16) as usize, captured);
+                    let packet = &body[20..20 + captured];
+                    let mut options = 20 + captured.next_multiple_of(4);
+                    while options + 4 <= body.len() {
+                        let code = u16_le(body, options);
+                        let len = usize::from(u16_le(body, options + 2));
+                        if code == 0 {
+                            break;
+                        }
+                        if code == OPTION_COMMENT {
+                            let text = &body[options + 4..options + 4 + len];
+                            capture.comments.push(String::from_utf8_lossy(text).into_owned());
+                        }
+                        options += 4 + len.next_multiple_of(4);
+                    }
+                    check_packet(packet, &mut capture);
+                }
+                other => panic!("unexpected block type {other:#x}"),
+            }
+            offset += total;
+        }
+        assert_eq!(offset, bytes.len());
+        capture
+    }
+
+    fn check_packet(packet: &[u8], capture: &mut CheckedCapture) {
+        assert_eq!(packet[0] >> 4, 4, "IPv4 packet");
+        assert_eq!(internet_checksum(&[&packet[..20]]), 0, "IPv4 header checksum");
+        assert_eq!(usize::from(u16::from_be_bytes([packet[2], packet[3]])), packet.len());
+        assert_eq!(packet[9], 6, "TCP protocol");
+        let tcp = &packet[20..];
+        let mut pseudo = Vec::new();
+        pseudo.extend_from_slice(&packet[12..20]);
+        pseudo.extend_from_slice(&[0, 6]);
+        pseudo.extend_from_slice(&(tcp.len() as u16).to_be_bytes());
+        assert_eq!(internet_checksum(&[&pseudo, tcp]), 0, "TCP checksum");
+        let flags = tcp[13];
+        if flags & TCP_SYN != 0 {
+            capture.syns += 1;
+        }
+        let payload = &tcp[usize::from(tcp[12] >> 4) * 4..];
+        let src_port = u16::from_be_bytes([tcp[0], tcp[1]]);
+        capture
+            .payloads
+            .entry(src_port)
+            .or_default()
+            .extend_from_slice(payload);
+        capture.packets += 1;
+    }
+
+    #[test]
+    fn pcapng_file_is_valid_and_carries_payloads() {
+        init_test("pcapng_file_is_valid_and_carries_payloads");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("tap.pcapng");
+        let sink = Arc::new(PcapngSink::create(&path).expect("create capture"));
+        let config = TapConfig::new(TapDirection::Both, 40, sink.clone())
+            .with_budget(Arc::new(TapBudget::new(usize::MAX)));
+        let (mut client, mut server) = tapped_pair(config);
+
+        future::block_on(client.write_all(b"hello over the wire")).expect("write");
+        let mut request = [0_u8; 19];
+        server.read_exact(&mut request).expect("server read");
+        let response = [0x5a_u8; 32];
+        server.write_all(&response).expect("server write");
+        let mut received = [0_u8; 32];
+        future::block_on(client.read_exact(&mut received)).expect("read");
+        sink.flush().expect("flush capture");
+
+        let bytes = std::fs::read(&path).expect("read capture");
+        let capture = check_pcapng(&bytes);
+        let client_port = server.peer_addr().expect("client addr").port();
+        let server_port = server.local_addr().expect("server addr").port();
+        assert_eq!(capture.syns, 2, "synthesized handshake");
+        assert_eq!(capture.payloads[&client_port], b"hello over the wire");
+        // 40-byte limit: 19 outbound bytes leave 21 for the response.
+        assert_eq!(capture.payloads[&server_port], response[..21]);
+        assert_eq!(capture.comments, vec!["tap truncated: connection byte limit reached"]);
+        assert!(capture.packets >= 6);
+        crate::test_complete!("pcapng_file_is_valid_and_carries_payloads");
+    }
+
+    #[test]
+    fn untapped_stream_has_no_tap_state() {
+        init_test("untapped_stream_has_no_tap_state");
+        let listener = net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let client =
+            net::TcpStream::connect(listener.local_addr().expect("addr")).expect("connect");
+        let _server = listener.accept().expect("accept");
+        let mut client = TcpStream::from_std(client).expect("wrap");
+        assert_eq!(client.tap_connection_id(), None);
+        future::block_on(client.write_all(b"untapped")).expect("write");
+        assert_eq!(client.tap_connection_id(), None);
+        crate::test_complete!("untapped_stream_has_no_tap_state");
+    }
+}
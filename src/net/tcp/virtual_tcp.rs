@@ -25,6 +25,9 @@
 
 use super::traits::{TcpListenerApi, TcpStreamApi};
 use crate::io::{AsyncRead, AsyncWrite, ReadBuf};
+use crate::net::fd_exhaustion::{
+    AcceptFdGuard, EmergencyFd, FdExhaustionConfig, FdExhaustionSnapshot, accept_clock_now,
+};
 use parking_lot::Mutex;
 use std::collections::VecDeque;
 use std::io;
@@ -434,6 +437,56 @@ impl TcpStreamApi for VirtualTcpStream {
 struct VirtualListenerState {
     connections: VecDeque<(VirtualTcpStream, SocketAddr)>,
     closed: bool,
+    /// Simulated full descriptor table (see `set_fd_exhausted`).
+    fd_exhausted: bool,
+    /// Descriptors freed while the table is full.
+    released_fds: usize,
+    /// Accepts that reached the simulated descriptor table.
+    accept_attempts: u64,
+}
+
+impl VirtualListenerState {
+    /// Claims a descriptor for an accepted connection or reservation.
+    fn take_fd(&mut self) -> bool {
+        if !self.fd_exhausted {
+            return true;
+        }
+        if self.released_fds == 0 {
+            return false;
+        }
+        self.released_fds -= 1;
+        true
+    }
+}
+
+/// Simulated emergency descriptor; dropping it frees one slot.
+struct VirtualFdReservation {
+    state: Arc<Mutex<VirtualListenerState>>,
+}
+
+impl Drop for VirtualFdReservation {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        if state.fd_exhausted {
+            state.released_fds += 1;
+        }
+    }
+}
+
+fn reserve_virtual_fd(state: &Arc<Mutex<VirtualListenerState>>) -> Option<EmergencyFd> {
+    if !state.lock().take_fd() {
+        return None;
+    }
+    Some(EmergencyFd::new(VirtualFdReservation {
+        state: Arc::clone(state),
+    }))
+}
+
+fn virtual_fd_exhausted_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::QuotaExceeded,
+        crate::error::Error::fd_exhausted("virtual accept"),
+    )
 }
 
 /// A virtual TCP listener for deterministic testing.
@@ -461,6 +514,7 @@ pub struct VirtualTcpListener {
     addr: SocketAddr,
     state: Arc<Mutex<VirtualListenerState>>,
     accept_waiters: Arc<AcceptWaiters>,
+    fd_guard: AcceptFdGuard,
 }
 
 impl Drop for VirtualTcpListener {
@@ -481,14 +535,85 @@ impl VirtualTcpListener {
     /// Create a new virtual listener bound to the given address.
     #[must_use]
     pub fn new(addr: SocketAddr) -> Self {
+        let state = Arc::new(Mutex::new(VirtualListenerState {
+            connections: VecDeque::with_capacity(VIRTUAL_TCP_ACCEPT_QUEUE_CAPACITY),
+            closed: false,
+            fd_exhausted: false,
+            released_fds: 0,
+            accept_attempts: 0,
+        }));
+        let fd_guard =
+            AcceptFdGuard::new(FdExhaustionConfig::default(), || reserve_virtual_fd(&state));
         Self {
             addr,
-            state: Arc::new(Mutex::new(VirtualListenerState {
-                connections: VecDeque::with_capacity(VIRTUAL_TCP_ACCEPT_QUEUE_CAPACITY),
-                closed: false,
-            })),
+            state,
             accept_waiters: Arc::new(AcceptWaiters::default()),
+            fd_guard,
+        }
+    }
+
+    /// Replaces the file descriptor exhaustion policy.
+    #[must_use]
+    pub fn with_fd_exhaustion_config(mut self, config: FdExhaustionConfig) -> Self {
+        self.fd_guard = AcceptFdGuard::new(config, || reserve_virtual_fd(&self.state));
+        self
+    }
+
+    /// Simulates a full descriptor table.
+    ///
+    /// While set, accepting a pending connection fails with a typed
+    /// `EMFILE`-style error unless a descriptor has been released, e.g. by
+    /// the listener spending its emergency reservation.
+    pub fn set_fd_exhausted(&self, exhausted: bool) {
+        let mut state = self.state.lock();
+        state.fd_exhausted = exhausted;
+        state.released_fds = 0;
+    }
+
+    /// Returns how many accepts reached the simulated descriptor table.
+    #[must_use]
+    pub fn accept_attempts(&self) -> u64 {
+        self.state.lock().accept_attempts
+    }
+
+    /// Returns the listener's file descriptor exhaustion counters.
+    #[must_use]
+    pub fn fd_exhaustion_snapshot(&self) -> FdExhaustionSnapshot {
+        self.fd_guard.snapshot()
+    }
+
+    /// Sheds one pending connection with the released emergency descriptor.
+    fn shed_pending_connection(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.connections.is_empty() || !state.take_fd() {
+            return false;
+        }
+        state.accept_attempts += 1;
+        let shed = state.connections.pop_front();
+        // Closing the shed connection frees its descriptor again.
+        state.released_fds += 1;
+        drop(state);
+        drop(shed);
+        true
+    }
+
+    fn poll_fd_exhausted(
+        &self,
+        cx: &mut Context<'_>,
+        now: crate::types::Time,
+    ) -> Poll<io::Result<(VirtualTcpStream, SocketAddr)>> {
+        let deadline = self.fd_guard.on_exhaustion(
+            now,
+            || self.shed_pending_connection(),
+            || reserve_virtual_fd(&self.state),
+        );
+        self.accept_waiters.register(cx.waker());
+        let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+        if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+            return Poll::Pending;
         }
+        self.accept_waiters.wake_others(cx.waker());
+        Poll::Ready(Err(virtual_fd_exhausted_error()))
     }
 
     /// Inject a connection into the listener's accept queue.
@@ -576,36 +701,22 @@ impl TcpListenerApi for VirtualTcpListener {
     fn accept(
         &self,
     ) -> impl std::future::Future<Output = io::Result<(Self::Stream, SocketAddr)>> + Send {
-        let state = Arc::clone(&self.state);
-        let accept_waiters = Arc::clone(&self.accept_waiters);
-        async move {
-            std::future::poll_fn(|cx| {
-                let mut guard = state.lock();
-                if guard.closed {
-                    drop(guard);
-                    accept_waiters.wake_others(cx.waker());
-                    return Poll::Ready(Err(io::Error::new(
-                        io::ErrorKind::NotConnected,
-                        "virtual listener closed",
-                    )));
-                }
-                if let Some(conn) = guard.connections.pop_front() {
-                    drop(guard);
-                    accept_waiters.wake_others(cx.waker());
-                    return Poll::Ready(Ok(conn));
-                }
-                drop(guard);
-                accept_waiters.register(cx.waker());
-                Poll::Pending
-            })
-            .await
-        }
+        std::future::poll_fn(|cx| TcpListenerApi::poll_accept(self, cx))
     }
 
     fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(Self::Stream, SocketAddr)>> {
         if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
             return Poll::Ready(Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled")));
         }
+        let now = accept_clock_now();
+        if let Some(deadline) = self.fd_guard.cooldown_deadline(now) {
+            self.accept_waiters.register(cx.waker());
+            let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+            if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+                return Poll::Pending;
+            }
+            self.accept_waiters.wake_others(cx.waker());
+        }
         let mut state = self.state.lock();
         if state.closed {
             drop(state);
@@ -615,6 +726,13 @@ impl TcpListenerApi for VirtualTcpListener {
                 "virtual listener closed",
             )));
         }
+        if !state.connections.is_empty() {
+            state.accept_attempts += 1;
+            if !state.take_fd() {
+                drop(state);
+                return self.poll_fd_exhausted(cx, now);
+            }
+        }
         if let Some(conn) = state.connections.pop_front() {
             drop(state);
             self.accept_waiters.wake_others(cx.waker());
@@ -649,10 +767,13 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::cx::Cx;
     use crate::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use crate::types::{Budget, RegionId, TaskId};
     use std::pin::Pin;
     use std::sync::Arc;
     use std::task::{Context, Waker};
+    use std::time::Duration;
 
     fn noop_waker() -> Waker {
         std::task::Waker::noop().clone()
@@ -901,6 +1022,141 @@ mod tests {
         assert_eq!(listener.pending_count(), 1);
     }
 
+    fn lab_timer_cx() -> (
+        Arc<crate::time::VirtualClock>,
+        crate::time::TimerDriverHandle,
+        Cx,
+    ) {
+        let clock = Arc::new(crate::time::VirtualClock::new());
+        let timer = crate::time::TimerDriverHandle::with_virtual_clock(clock.clone());
+        let cx = Cx::new_with_drivers(
+            RegionId::new_for_test(0, 1),
+            TaskId::new_for_test(0, 0),
+            Budget::INFINITE,
+            None,
+            None,
+            None,
+            Some(timer.clone()),
+            None,
+        );
+        (clock, timer, cx)
+    }
+
+    #[test]
+    fn fd_exhaustion_sheds_pending_connection_and_cools_down() {
+        let (clock, timer, cx) = lab_timer_cx();
+        let _guard = Cx::set_current(Some(cx));
+        let listener = VirtualTcpListener::new(addr("127.0.0.1:8080"));
+        assert!(listener.fd_exhaustion_snapshot().emergency_fd_reserved);
+
+        let (mut shed_client, server) =
+            VirtualTcpStream::pair(addr("127.0.0.1:9000"), addr("127.0.0.1:8080"));
+        listener.inject_connection(server, addr("127.0.0.1:9000"));
+        listener.set_fd_exhausted(true);
+
+        let (wakes, waker) = count_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(listener.poll_accept(&mut cx).is_pending());
+
+        // The emergency descriptor was spent to accept-and-close the client.
+        assert_eq!(listener.pending_count(), 0);
+        let mut buf = [0u8; 8];
+        let mut read_buf = ReadBuf::new(&mut buf);
+        let eof = Pin::new(&mut shed_client).poll_read(&mut cx, &mut read_buf);
+        assert!(matches!(eof, Poll::Ready(Ok(()))));
+        assert!(read_buf.filled().is_empty(), "shed client should see EOF");
+
+        let snap = listener.fd_exhaustion_snapshot();
+        assert_eq!(snap.exhaustion_events, 1);
+        assert_eq!(snap.emergency_sheds, 1);
+        assert_eq!(snap.events_emitted, 1);
+        assert!(snap.emergency_fd_reserved, "reserve re-acquired after shed");
+        assert_eq!(
+            snap.cooldown_until,
+            Some(crate::types::Time::from_millis(100))
+        );
+
+        // Polls inside the cooldown never reach accept and arm one timer.
+        let (_client, server) =
+            VirtualTcpStream::pair(addr("127.0.0.1:9001"), addr("127.0.0.1:8080"));
+        listener.inject_connection(server, addr("127.0.0.1:9001"));
+        let attempts = listener.accept_attempts();
+        let baseline_wakes = wakes.0.load(Ordering::Relaxed);
+        for _ in 0..64 {
+            assert!(listener.poll_accept(&mut cx).is_pending());
+        }
+        assert_eq!(listener.accept_attempts(), attempts);
+        assert_eq!(timer.pending_count(), 1);
+
+        clock.advance(Duration::from_millis(99).as_nanos() as u64);
+        assert_eq!(timer.process_timers(), 0);
+        assert_eq!(wakes.0.load(Ordering::Relaxed), baseline_wakes);
+
+        clock.advance(Duration::from_millis(1).as_nanos() as u64);
+        assert_eq!(timer.process_timers(), 1);
+        assert!(wakes.0.load(Ordering::Relaxed) > baseline_wakes);
+
+        listener.set_fd_exhausted(false);
+        let accepted = listener.poll_accept(&mut cx);
+        assert!(matches!(accepted, Poll::Ready(Ok((_, peer))) if peer == addr("127.0.0.1:9001")));
+    }
+
+    #[test]
+    fn fd_exhaustion_without_emergency_fd_leaves_connection_queued() {
+        let (clock, timer, cx) = lab_timer_cx();
+        let _guard = Cx::set_current(Some(cx));
+        let listener = VirtualTcpListener::new(addr("127.0.0.1:8080")).with_fd_exhaustion_config(
+            FdExhaustionConfig {
+                emergency_fd: false,
+                cooldown: Duration::from_millis(250),
+                ..FdExhaustionConfig::default()
+            },
+        );
+        let (_client, server) =
+            VirtualTcpStream::pair(addr("127.0.0.1:9000"), addr("127.0.0.1:8080"));
+        listener.inject_connection(server, addr("127.0.0.1:9000"));
+        listener.set_fd_exhausted(true);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(listener.poll_accept(&mut cx).is_pending());
+        assert_eq!(listener.pending_count(), 1);
+        assert_eq!(listener.fd_exhaustion_snapshot().emergency_sheds, 0);
+
+        // Each cooldown expiry retries accept exactly once.
+        for round in 1..=3u64 {
+            clock.advance(Duration::from_millis(250).as_nanos() as u64);
+            timer.process_timers();
+            assert!(listener.poll_accept(&mut cx).is_pending());
+            assert!(listener.poll_accept(&mut cx).is_pending());
+            assert_eq!(listener.accept_attempts(), round + 1);
+        }
+        let snap = listener.fd_exhaustion_snapshot();
+        assert_eq!(snap.exhaustion_events, 4);
+        assert_eq!(snap.events_emitted, 1, "events are rate limited to 1/s");
+        assert_eq!(snap.events_suppressed, 3);
+    }
+
+    #[test]
+    fn fd_exhaustion_without_timer_returns_typed_error() {
+        let listener = VirtualTcpListener::new(addr("127.0.0.1:8080"));
+        let (_client, server) =
+            VirtualTcpStream::pair(addr("127.0.0.1:9000"), addr("127.0.0.1:8080"));
+        listener.inject_connection(server, addr("127.0.0.1:9000"));
+        listener.set_fd_exhausted(true);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let Poll::Ready(Err(err)) = listener.poll_accept(&mut cx) else {
+            panic!("expected a typed fd exhaustion error without a timer driver");
+        };
+        assert!(crate::error::is_fd_exhaustion(&err));
+        let typed = crate::Error::from_io(&err).expect("typed error");
+        assert_eq!(typed.kind(), crate::error::ErrorKind::FdExhausted);
+        assert!(typed.is_retryable());
+        assert_eq!(listener.fd_exhaustion_snapshot().emergency_sheds, 1);
+    }
+
     #[test]
     fn virtual_listener_drop_marks_closed() {
         let listener = VirtualTcpListener::new(addr("127.0.0.1:8080"));
@@ -1371,6 +1371,7 @@ pub struct UdpSocket {
 impl UdpSocket {
     /// Bind to the given address.
     pub async fn bind<A: ToSocketAddrs + Send + 'static>(addr: A) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         #[cfg(target_arch = "wasm32")]
         {
             let _ = addr;
@@ -133,6 +133,7 @@ impl UnixDatagram {
     /// let socket = UnixDatagram::bind("/tmp/my_datagram.sock")?;
     /// ```
     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         let path = path.as_ref();
 
         super::listener::reject_non_socket_bind_path(path)?;
@@ -160,6 +161,7 @@ impl UnixDatagram {
     /// ```
     #[cfg(target_os = "linux")]
     pub fn bind_abstract(name: &[u8]) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         use std::os::linux::net::SocketAddrExt;
 
         let addr = SocketAddr::from_abstract_name(name)?;
@@ -28,6 +28,10 @@
 //! kernel handles it automatically.
 
 use crate::cx::Cx;
+use crate::error::{is_fd_exhaustion, map_fd_exhaustion};
+use crate::net::fd_exhaustion::{
+    AcceptFdGuard, EmergencyFd, FdExhaustionConfig, FdExhaustionSnapshot, accept_clock_now,
+};
 use crate::net::unix::stream::UnixStream;
 use crate::runtime::io_driver::IoRegistration;
 use crate::runtime::reactor::Interest;
@@ -308,6 +312,8 @@ pub struct UnixListener {
     path: Option<PathBuf>,
     /// Device/inode identity captured at bind time for safe cleanup.
     cleanup_identity: Option<SocketFileIdentity>,
+    /// Cooldown, emergency descriptor and counters for fd exhaustion.
+    fd_guard: AcceptFdGuard,
 }
 
 impl UnixListener {
@@ -333,6 +339,7 @@ impl UnixListener {
     /// let listener = UnixListener::bind("/tmp/my_socket.sock").await?;
     /// ```
     pub async fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         let path = path.as_ref();
 
         reject_non_socket_bind_path(path)?;
@@ -360,11 +367,13 @@ impl UnixListener {
     /// ```
     #[cfg(target_os = "linux")]
     pub async fn bind_abstract(name: &[u8]) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         use std::os::linux::net::SocketAddrExt;
 
         let addr = SocketAddr::from_abstract_name(name)?;
         let inner = net::UnixListener::bind_addr(&addr)?;
         inner.set_nonblocking(true)?;
+        let fd_guard = default_fd_guard(&inner);
 
         Ok(Self {
             inner,
@@ -372,6 +381,7 @@ impl UnixListener {
             path: None, // No filesystem path for abstract sockets
             cleanup_identity: None,
             registration: Mutex::new(None), // Lazy registration on first poll
+            fd_guard,
         })
     }
 
@@ -406,11 +416,22 @@ impl UnixListener {
         if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
             return Poll::Ready(Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled")));
         }
+        let now = accept_clock_now();
+        if let Some(deadline) = self.fd_guard.cooldown_deadline(now) {
+            self.accept_waiters.register(cx.waker());
+            let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+            if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+                return Poll::Pending;
+            }
+            self.accept_waiters.wake_others(cx.waker());
+        }
         match self.inner.accept() {
             Ok((stream, addr)) => {
+                self.fd_guard.observe_usage(now);
                 self.accept_waiters.wake_others(cx.waker());
                 Poll::Ready(UnixStream::from_std(stream).map(|stream| (stream, addr)))
             }
+            Err(e) if is_fd_exhaustion(&e) => self.poll_fd_exhausted(cx, now, e),
             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
                 self.accept_waiters.register(cx.waker());
                 if let Err(err) = self.register_interest() {
@@ -427,6 +448,7 @@ impl UnixListener {
                         Poll::Ready(UnixStream::from_std(stream).map(|stream| (stream, addr)))
                     }
                     Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => Poll::Pending,
+                    Err(err) if is_fd_exhaustion(&err) => self.poll_fd_exhausted(cx, now, err),
                     Err(err) => {
                         self.accept_waiters.wake_others(cx.waker());
                         Poll::Ready(Err(err))
@@ -529,6 +551,7 @@ impl UnixListener {
     /// ```
     pub fn from_std(listener: net::UnixListener) -> io::Result<Self> {
         listener.set_nonblocking(true)?;
+        let fd_guard = default_fd_guard(&listener);
 
         Ok(Self {
             accept_waiters: Arc::new(AcceptWaiters::default()),
@@ -536,6 +559,7 @@ impl UnixListener {
             path: None, // Don't clean up sockets we didn't create
             cleanup_identity: None,
             registration: Mutex::new(None), // Lazy registration on first poll
+            fd_guard,
         })
     }
 
@@ -583,6 +607,7 @@ impl UnixListener {
         F: FnOnce(&net::UnixListener) -> io::Result<()>,
     {
         let (inner, cleanup_identity) = finalize_bound_socket(path, inner, configure)?;
+        let fd_guard = default_fd_guard(&inner);
 
         Ok(Self {
             accept_waiters: Arc::new(AcceptWaiters::default()),
@@ -590,8 +615,58 @@ impl UnixListener {
             path: Some(path.to_path_buf()),
             cleanup_identity,
             registration: Mutex::new(None), // Lazy registration on first poll
+            fd_guard,
         })
     }
+
+    /// Replaces the file descriptor exhaustion policy.
+    ///
+    /// See [`FdExhaustionConfig`] and
+    /// [`TcpListener::with_fd_exhaustion_config`](crate::net::TcpListener::with_fd_exhaustion_config).
+    #[must_use]
+    pub fn with_fd_exhaustion_config(mut self, config: FdExhaustionConfig) -> Self {
+        self.fd_guard = AcceptFdGuard::new(config, || reserve_emergency_fd(&self.inner));
+        self
+    }
+
+    /// Returns the listener's file descriptor exhaustion counters.
+    #[must_use]
+    pub fn fd_exhaustion_snapshot(&self) -> FdExhaustionSnapshot {
+        self.fd_guard.snapshot()
+    }
+
+    /// Sheds the pending connection with the emergency descriptor and enters
+    /// the accept cooldown, or returns the typed error without a timer driver.
+    fn poll_fd_exhausted(
+        &self,
+        cx: &mut Context<'_>,
+        now: crate::types::Time,
+        err: io::Error,
+    ) -> Poll<io::Result<(UnixStream, SocketAddr)>> {
+        let deadline = self.fd_guard.on_exhaustion(
+            now,
+            || self.inner.accept().is_ok(),
+            || reserve_emergency_fd(&self.inner),
+        );
+        self.accept_waiters.register(cx.waker());
+        let accept_waker = Waker::from(Arc::clone(&self.accept_waiters));
+        if self.fd_guard.arm_cooldown_wake(deadline, accept_waker) {
+            return Poll::Pending;
+        }
+        self.accept_waiters.wake_others(cx.waker());
+        Poll::Ready(Err(map_fd_exhaustion(err)))
+    }
+}
+
+fn default_fd_guard(inner: &net::UnixListener) -> AcceptFdGuard {
+    AcceptFdGuard::new(FdExhaustionConfig::default(), || {
+        reserve_emergency_fd(inner)
+    })
+}
+
+/// Reserves the emergency descriptor as a duplicate of the listening socket.
+fn reserve_emergency_fd(inner: &net::UnixListener) -> Option<EmergencyFd> {
+    inner.try_clone().ok().map(EmergencyFd::new)
 }
 
 fn fallback_rewake_waiters(accept_waiters: &Arc<AcceptWaiters>) {
@@ -8,6 +8,11 @@
 //! - [`UnixListener`]: Accepts incoming Unix socket connections
 //! - [`UnixStream`]: Bidirectional byte stream for client connections
 //! - `UnixDatagram`: Connectionless datagram socket for local IPC
+//! - [`VirtualUnixStream`]: In-memory stream with scriptable peer credentials
+//!   for lab tests
+//!
+//! File descriptors can be handed to another process with
+//! [`UnixStream::send_with_fd`] and [`UnixStream::recv_with_fd`].
 //!
 //! # Example
 //!
@@ -48,6 +53,7 @@ pub mod datagram;
 pub mod listener;
 pub mod split;
 pub mod stream;
+pub mod virtual_unix;
 
 pub use ancillary::{
     AncillaryMessage, AncillaryMessages, ScmRights, SocketAncillary, ancillary_space_for_fds,
@@ -56,3 +62,4 @@ pub use datagram::UnixDatagram;
 pub use listener::{Incoming, UnixListener};
 pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
 pub use stream::{UCred, UnixStream};
+pub use virtual_unix::VirtualUnixStream;
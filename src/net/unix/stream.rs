@@ -23,9 +23,11 @@ use crate::runtime::io_driver::IoRegistration;
 use crate::runtime::reactor::Interest;
 use nix::sys::socket::{self, ControlMessage, MsgFlags};
 use parking_lot::Mutex;
+use smallvec::SmallVec;
 use socket2::{Domain, SockAddr, Socket, Type};
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::net::Shutdown;
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd};
 use std::os::unix::io::RawFd;
 use std::os::unix::net::{self, SocketAddr};
 use std::path::Path;
@@ -228,6 +230,7 @@ impl UnixStream {
     /// let stream = UnixStream::connect("/tmp/my_socket.sock").await?;
     /// ```
     pub async fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        crate::cx::attenuation::check_ambient_io(crate::cx::Capability::Net)?;
         let domain = Domain::UNIX;
         let socket = Socket::new(domain, Type::STREAM, None)?;
         socket.set_nonblocking(true)?;
@@ -504,8 +507,6 @@ impl UnixStream {
         buf: &[u8],
         ancillary: &mut crate::net::unix::SocketAncillary,
     ) -> io::Result<usize> {
-        use std::os::unix::io::AsRawFd;
-
         std::future::poll_fn(|cx| {
             if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
                 return cancelled_poll();
@@ -573,8 +574,6 @@ impl UnixStream {
         buf: &mut [u8],
         ancillary: &mut crate::net::unix::SocketAncillary,
     ) -> io::Result<usize> {
-        use std::os::unix::io::AsRawFd;
-
         std::future::poll_fn(|cx| {
             if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
                 return cancelled_poll();
@@ -591,6 +590,87 @@ impl UnixStream {
         .await
     }
 
+    /// Sends `buf` together with the file descriptors in `fds` (`SCM_RIGHTS`).
+    ///
+    /// The descriptors stay owned by the caller; the kernel installs
+    /// duplicates in the receiving process. At least one byte of `buf` must
+    /// be sent for the descriptors to be delivered, so an empty `buf` is
+    /// rejected when `fds` is non-empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the send fails or `buf` is empty while `fds` is not.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::os::fd::AsFd;
+    ///
+    /// let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    /// channel.send_with_fd(b"listener", &[listener.as_fd()]).await?;
+    /// ```
+    #[allow(clippy::future_not_send)]
+    pub async fn send_with_fd(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        if buf.is_empty() && !fds.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "file descriptors require at least one byte of payload",
+            ));
+        }
+        let raw: SmallVec<[RawFd; 4]> = fds.iter().map(AsRawFd::as_raw_fd).collect();
+        let mut ancillary = crate::net::unix::SocketAncillary::new(0);
+        ancillary.add_fds(&raw);
+        self.send_with_ancillary(buf, &mut ancillary).await
+    }
+
+    /// Receives data into `buf` along with up to `max_fds` passed descriptors.
+    ///
+    /// Received descriptors are returned as [`OwnedFd`]s and are closed when
+    /// dropped. If the peer sent more than `max_fds` descriptors, the kernel
+    /// discards the excess; the call fails with [`io::ErrorKind::InvalidData`]
+    /// after closing any descriptors that did arrive, so a short control
+    /// buffer is never silently accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the receive fails or the descriptor list was
+    /// truncated.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut buf = [0u8; 16];
+    /// let (n, fds) = channel.recv_with_fd(&mut buf, 1).await?;
+    /// let listener = std::net::TcpListener::from(fds.into_iter().next().unwrap());
+    /// ```
+    #[allow(clippy::future_not_send)]
+    pub async fn recv_with_fd(
+        &self,
+        buf: &mut [u8],
+        max_fds: usize,
+    ) -> io::Result<(usize, Vec<OwnedFd>)> {
+        let mut cmsg_buf = vec![0u8; crate::net::unix::ancillary_space_for_fds(max_fds)];
+
+        std::future::poll_fn(|cx| {
+            if crate::cx::Cx::with_current(|c| c.checkpoint().is_err()).unwrap_or(false) {
+                return cancelled_poll();
+            }
+
+            match recvmsg_with_raw_ancillary(self.inner.as_raw_fd(), buf, &mut cmsg_buf) {
+                Ok((_, _, true)) => Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "received file descriptors exceeded max_fds",
+                ))),
+                Ok((n, fds, false)) => Poll::Ready(Ok((n, fds))),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.pending_on_interest(cx, Interest::READABLE)
+                }
+                Err(e) => Poll::Ready(Err(e)),
+            }
+        })
+        .await
+    }
+
     /// Splits the stream into borrowed read and write halves.
     ///
     /// The halves borrow the stream and can be used concurrently for
@@ -628,6 +708,37 @@ impl UnixStream {
         let registration = self.registration.lock().take();
         OwnedReadHalf::new_pair(self.inner, registration)
     }
+
+    /// Polls a nonblocking read through a shared reference.
+    ///
+    /// Used by transports that keep the stream behind an `Arc` and only read
+    /// short control bytes from it, such as the shared-memory IPC channel.
+    pub(crate) fn poll_read_shared(
+        &self,
+        cx: &Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            match (&*self.inner).read(buf) {
+                Ok(n) => return Poll::Ready(Ok(n)),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    return self.pending_on_interest(cx, Interest::READABLE);
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    /// Writes without blocking and without raising `SIGPIPE` if the peer is
+    /// gone; a closed peer surfaces as [`io::ErrorKind::BrokenPipe`].
+    pub(crate) fn try_write_nosignal(&self, buf: &[u8]) -> io::Result<usize> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let flags = MsgFlags::MSG_NOSIGNAL | MsgFlags::MSG_DONTWAIT;
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let flags = MsgFlags::MSG_DONTWAIT;
+        socket::send(self.inner.as_raw_fd(), buf, flags).map_err(nix_to_io)
+    }
 }
 
 impl AsyncRead for UnixStream {
@@ -829,7 +940,12 @@ fn recv_with_ancillary_impl(
     }?;
 
     if !received_fds.is_empty() {
-        ancillary.push_received_fds(&received_fds);
+        // Ownership of the raw descriptors passes to the ancillary caller.
+        let raw: SmallVec<[RawFd; 4]> = received_fds
+            .into_iter()
+            .map(IntoRawFd::into_raw_fd)
+            .collect();
+        ancillary.push_received_fds(&raw);
     }
     if truncated {
         ancillary.mark_truncated();
@@ -843,7 +959,7 @@ fn recvmsg_with_raw_ancillary(
     fd: RawFd,
     buf: &mut [u8],
     cmsg_buf: &mut [u8],
-) -> io::Result<(usize, Vec<RawFd>, bool)> {
+) -> io::Result<(usize, Vec<OwnedFd>, bool)> {
     let mut iov = libc::iovec {
         iov_base: buf.as_mut_ptr().cast(),
         iov_len: buf.len(),
@@ -857,7 +973,10 @@ fn recvmsg_with_raw_ancillary(
     // SAFETY: `iov` points at the caller-provided mutable data buffer for the
     // duration of the syscall, `cmsg_buf` is initialized and writable for
     // `control_len` bytes, and parsing walks only headers accepted by libc's
-    // CMSG_* helpers bounded by the kernel-written `msg_controllen`.
+    // CMSG_* helpers bounded by the kernel-written `msg_controllen`. Each
+    // non-negative SCM_RIGHTS entry is a fresh descriptor installed by the
+    // kernel for this process, so wrapping it in `OwnedFd` exactly once is
+    // sound and guarantees it is closed on every path.
     let (bytes, received_fds, truncated) = unsafe {
         let mut msg = std::mem::zeroed::<libc::msghdr>();
         msg.msg_iov = &mut iov;
@@ -903,7 +1022,7 @@ fn recvmsg_with_raw_ancillary(
                     for index in 0..fd_count {
                         let received_fd = std::ptr::read_unaligned(data.add(index));
                         if received_fd >= 0 {
-                            received_fds.push(received_fd);
+                            received_fds.push(OwnedFd::from_raw_fd(received_fd));
                         }
                     }
                 }
@@ -1287,6 +1406,64 @@ mod tests {
         crate::test_complete!("test_send_recv_with_ancillary");
     }
 
+    #[test]
+    fn send_recv_with_fd_hands_off_a_usable_listener() {
+        use std::os::fd::AsFd;
+
+        init_test("send_recv_with_fd_hands_off_a_usable_listener");
+        futures_lite::future::block_on(async {
+            let (tx, rx) = UnixStream::pair().expect("pair failed");
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind listener");
+            let addr = listener.local_addr().expect("listener addr");
+
+            let sent = tx
+                .send_with_fd(b"L", &[listener.as_fd()])
+                .await
+                .expect("send_with_fd failed");
+            crate::assert_with_log!(sent == 1, "sent bytes", 1, sent);
+            // The sender's copy can go away; the receiver holds its own.
+            drop(listener);
+
+            let mut buf = [0u8; 4];
+            let (n, fds) = rx.recv_with_fd(&mut buf, 1).await.expect("recv_with_fd");
+            crate::assert_with_log!(n == 1 && buf[0] == b'L', "payload", b'L', buf[0]);
+            crate::assert_with_log!(fds.len() == 1, "fd count", 1, fds.len());
+
+            let handed_off = std::net::TcpListener::from(fds.into_iter().next().expect("one fd"));
+            let handed_addr = handed_off.local_addr().expect("handed addr");
+            crate::assert_with_log!(handed_addr == addr, "same socket", addr, handed_addr);
+
+            let client = std::net::TcpStream::connect(addr).expect("connect to handed listener");
+            let (accepted, peer) = handed_off.accept().expect("accept on handed listener");
+            let client_local = client.local_addr().expect("client addr");
+            crate::assert_with_log!(peer == client_local, "accepted peer", client_local, peer);
+            drop(accepted);
+        });
+        crate::test_complete!("send_recv_with_fd_hands_off_a_usable_listener");
+    }
+
+    #[test]
+    fn send_with_fd_rejects_empty_payload() {
+        use std::os::fd::AsFd;
+
+        init_test("send_with_fd_rejects_empty_payload");
+        futures_lite::future::block_on(async {
+            let (tx, _rx) = UnixStream::pair().expect("pair failed");
+            let (pipe_read, _pipe_write) = nix::unistd::pipe().expect("pipe failed");
+            let err = tx
+                .send_with_fd(b"", &[pipe_read.as_fd()])
+                .await
+                .expect_err("empty payload with fds must fail");
+            crate::assert_with_log!(
+                err.kind() == io::ErrorKind::InvalidInput,
+                "error kind",
+                io::ErrorKind::InvalidInput,
+                err.kind()
+            );
+        });
+        crate::test_complete!("send_with_fd_rejects_empty_payload");
+    }
+
     #[test]
     fn recv_with_ancillary_reports_truncation_via_msg_ctrunc() {
         use crate::net::unix::{AncillaryMessage, SocketAncillary};
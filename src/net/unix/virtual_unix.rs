@@ -0,0 +1,207 @@
+//! Virtual Unix domain socket streams for deterministic testing.
+//!
+//! [`VirtualUnixStream`] is the lab counterpart of [`UnixStream`]: an
+//! in-memory byte pipe with the same [`AsyncRead`]/[`AsyncWrite`] surface and
+//! a scriptable [`peer_cred`](VirtualUnixStream::peer_cred), so peer
+//! authentication logic can be exercised without real sockets or real UIDs.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use asupersync::net::unix::{UCred, VirtualUnixStream};
+//!
+//! let sidecar = UCred { uid: 1000, gid: 1000, pid: Some(42) };
+//! let main = UCred { uid: 0, gid: 0, pid: Some(1) };
+//! let (to_main, from_sidecar) = VirtualUnixStream::pair_with_credentials(sidecar, main);
+//!
+//! assert_eq!(from_sidecar.peer_cred()?.uid, 1000);
+//! ```
+//!
+//! [`UnixStream`]: super::UnixStream
+
+use super::UCred;
+use crate::io::{AsyncRead, AsyncWrite, ReadBuf};
+use crate::net::tcp::VirtualTcpStream;
+use parking_lot::Mutex;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Credentials reported for virtual streams created without explicit ones.
+const DEFAULT_VIRTUAL_CRED: UCred = UCred {
+    uid: 0,
+    gid: 0,
+    pid: None,
+};
+
+/// An in-memory Unix stream with scriptable peer credentials.
+///
+/// Each side of a pair reports the *other* side's credentials from
+/// [`peer_cred`](Self::peer_cred). Credentials can be replaced at any point
+/// with [`set_local_cred`](Self::set_local_cred) to script identity changes
+/// or failures mid-test.
+pub struct VirtualUnixStream {
+    inner: VirtualTcpStream,
+    local_cred: Arc<Mutex<Option<UCred>>>,
+    peer_cred: Arc<Mutex<Option<UCred>>>,
+}
+
+impl std::fmt::Debug for VirtualUnixStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualUnixStream")
+            .field("local_cred", &*self.local_cred.lock())
+            .field("peer_cred", &*self.peer_cred.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+impl VirtualUnixStream {
+    /// Creates a connected pair whose sides both report default credentials
+    /// (`uid = 0`, `gid = 0`, no pid).
+    #[must_use]
+    pub fn pair() -> (Self, Self) {
+        Self::pair_with_credentials(DEFAULT_VIRTUAL_CRED, DEFAULT_VIRTUAL_CRED)
+    }
+
+    /// Creates a connected pair where the first stream's process is
+    /// `a_cred` and the second's is `b_cred`.
+    ///
+    /// The first stream's [`peer_cred`](Self::peer_cred) therefore returns
+    /// `b_cred`, and vice versa.
+    #[must_use]
+    pub fn pair_with_credentials(a_cred: UCred, b_cred: UCred) -> (Self, Self) {
+        // Unix sockets have no IP addresses; the inner transport only needs
+        // distinct placeholders.
+        let (a, b) = VirtualTcpStream::pair(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 1)),
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 2)),
+        );
+        let a_slot = Arc::new(Mutex::new(Some(a_cred)));
+        let b_slot = Arc::new(Mutex::new(Some(b_cred)));
+        (
+            Self {
+                inner: a,
+                local_cred: Arc::clone(&a_slot),
+                peer_cred: Arc::clone(&b_slot),
+            },
+            Self {
+                inner: b,
+                local_cred: b_slot,
+                peer_cred: a_slot,
+            },
+        )
+    }
+
+    /// Returns the scripted credentials of the peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::Unsupported`] if the peer's credentials were
+    /// cleared with `set_local_cred(None)`, mirroring platforms that cannot
+    /// report them.
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        (*self.peer_cred.lock()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "peer credentials unavailable on virtual unix stream",
+            )
+        })
+    }
+
+    /// Replaces the credentials this side presents to its peer.
+    ///
+    /// `None` makes the peer's [`peer_cred`](Self::peer_cred) fail.
+    pub fn set_local_cred(&self, cred: Option<UCred>) {
+        *self.local_cred.lock() = cred;
+    }
+}
+
+impl AsyncRead for VirtualUnixStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for VirtualUnixStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn scripted_credentials_are_reported_to_the_peer() {
+        init_test("scripted_credentials_are_reported_to_the_peer");
+        let sidecar = UCred {
+            uid: 1000,
+            gid: 100,
+            pid: Some(42),
+        };
+        let main = UCred {
+            uid: 0,
+            gid: 0,
+            pid: Some(1),
+        };
+        let (to_main, from_sidecar) = VirtualUnixStream::pair_with_credentials(sidecar, main);
+
+        let seen_by_main = from_sidecar.peer_cred().expect("peer cred");
+        crate::assert_with_log!(
+            seen_by_main == sidecar,
+            "main sees sidecar",
+            sidecar,
+            seen_by_main
+        );
+        let seen_by_sidecar = to_main.peer_cred().expect("peer cred");
+        crate::assert_with_log!(
+            seen_by_sidecar == main,
+            "sidecar sees main",
+            main,
+            seen_by_sidecar
+        );
+
+        to_main.set_local_cred(None);
+        let cleared = from_sidecar.peer_cred().is_err();
+        crate::assert_with_log!(cleared, "cleared cred errors", true, cleared);
+        crate::test_complete!("scripted_credentials_are_reported_to_the_peer");
+    }
+
+    #[test]
+    fn virtual_pair_carries_bytes() {
+        init_test("virtual_pair_carries_bytes");
+        futures_lite::future::block_on(async {
+            let (mut a, mut b) = VirtualUnixStream::pair();
+            a.write_all(b"ping").await.expect("write");
+            let mut buf = [0u8; 4];
+            b.read_exact(&mut buf).await.expect("read");
+            crate::assert_with_log!(&buf == b"ping", "payload", b"ping", buf);
+        });
+        crate::test_complete!("virtual_pair_carries_bytes");
+    }
+}
@@ -1,59 +1,201 @@
 //! In-memory log collector.
 //!
-//! Stores log entries in a ring buffer for retrieval and analysis.
+//! Stores log entries in a ring buffer for retrieval and analysis. An
+//! optional [`EventRateLimiter`] suppresses and summarises log storms before
+//! they reach the buffer, and an optional [`RegionTelemetry`] stamps entries
+//! with their region's name and applies its event quota. Each collector also
+//! owns a [`LogControl`] that overrides its level and sampling at runtime.
 
 use super::entry::LogEntry;
 use super::level::LogLevel;
+use super::log_control::LogControl;
+use super::rate_limit::{EventRateLimiter, RegionLimit};
+use super::tenant::{REGION_NAME_FIELD, RegionScope, RegionTelemetry};
+use crate::types::Time;
 use parking_lot::Mutex;
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 /// A thread-safe collector for log entries.
 #[derive(Debug, Clone)]
 pub struct LogCollector {
     inner: Arc<Mutex<CollectorInner>>,
+    /// Kept outside the buffer lock so filtered and rate-limited entries are
+    /// rejected without taking it.
+    min_level: Arc<AtomicU8>,
+    rate_limiter: Option<EventRateLimiter>,
+    telemetry: Option<RegionTelemetry>,
+    control: LogControl,
 }
 
 #[derive(Debug)]
-struct CollectorInner {
+pub(super) struct CollectorInner {
     entries: VecDeque<LogEntry>,
     capacity: usize,
-    min_level: LogLevel,
+}
+
+impl CollectorInner {
+    pub(super) fn push(&mut self, entry: LogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+pub(super) const fn level_from_rank(rank: u8) -> LogLevel {
+    match rank {
+        0 => LogLevel::Trace,
+        1 => LogLevel::Debug,
+        2 => LogLevel::Info,
+        3 => LogLevel::Warn,
+        _ => LogLevel::Error,
+    }
 }
 
 impl LogCollector {
     /// Creates a new collector with default capacity (1000).
     #[must_use]
     pub fn new(capacity: usize) -> Self {
+        let inner = Arc::new(Mutex::new(CollectorInner {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }));
+        let min_level = Arc::new(AtomicU8::new(LogLevel::Info as u8));
+        let control = LogControl::new(Arc::clone(&min_level), Arc::clone(&inner));
         Self {
-            inner: Arc::new(Mutex::new(CollectorInner {
-                entries: VecDeque::with_capacity(capacity),
-                capacity,
-                min_level: LogLevel::Info,
-            })),
+            inner,
+            min_level,
+            rate_limiter: None,
+            telemetry: None,
+            control,
         }
     }
 
     /// Sets the minimum log level to record.
     #[must_use]
     pub fn with_min_level(self, level: LogLevel) -> Self {
-        self.inner.lock().min_level = level;
+        self.min_level.store(level as u8, Ordering::Relaxed);
         self
     }
 
+    /// Rate-limits entries logged through this handle and its later clones.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: EventRateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Returns the attached rate limiter, if any.
+    #[must_use]
+    pub fn rate_limiter(&self) -> Option<&EventRateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Attributes entries to region namespaces: entries whose context names
+    /// a registered region carry its name in [`REGION_NAME_FIELD`], and its
+    /// event quota feeds the rate limiter unless the `Cx` installed a limit.
+    #[must_use]
+    pub fn with_region_telemetry(mut self, telemetry: RegionTelemetry) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Returns the attached region telemetry, if any.
+    #[must_use]
+    pub fn region_telemetry(&self) -> Option<&RegionTelemetry> {
+        self.telemetry.as_ref()
+    }
+
+    /// Returns the runtime level and sampling control shared by this
+    /// collector's clones.
+    #[must_use]
+    pub fn control(&self) -> &LogControl {
+        &self.control
+    }
+
     /// Logs an entry if it meets the minimum level.
+    ///
+    /// With a rate limiter attached or a [`LogControl`] override in force,
+    /// the entry's timestamp (or the ambient clock, if unset) drives the
+    /// limiter and override expiry.
     pub fn log(&self, entry: LogEntry) {
-        let mut inner = self.inner.lock();
-        if !entry.level().is_enabled_at(inner.min_level) {
+        let timed = self.rate_limiter.is_some() || self.control.is_active();
+        let now = if timed && entry.timestamp() == Time::ZERO {
+            crate::time::wall_now()
+        } else {
+            entry.timestamp()
+        };
+        self.log_scoped(entry, None, now);
+    }
+
+    /// Logs an entry on behalf of a `Cx`, whose region limit and clock feed
+    /// the rate limiter.
+    pub(crate) fn log_scoped(&self, entry: LogEntry, scope: Option<RegionLimit>, now: Time) {
+        let min_level = self.min_level();
+        let controlled = self.control.is_active();
+        if !controlled && !entry.level().is_enabled_at(min_level) {
             return;
         }
-        if inner.capacity == 0 {
+        let tenant = self
+            .telemetry
+            .as_ref()
+            .and_then(|telemetry| telemetry.resolve(&entry));
+        if controlled && !self.control.admit(&entry, tenant.as_ref().map(RegionScope::name), now) {
             return;
         }
-        if inner.entries.len() >= inner.capacity {
-            inner.entries.pop_front();
+        let quota = match (&tenant, scope) {
+            (Some(tenant), None) => tenant.event_limit(),
+            _ => None,
+        };
+        let scope = scope.or(quota);
+        let entry = match &tenant {
+            Some(tenant) => entry.with_field(REGION_NAME_FIELD, tenant.name()),
+            None => entry,
+        };
+        let mut summary = None;
+        if let Some(limiter) = &self.rate_limiter {
+            let mut admission = limiter.admit(&entry, scope, now);
+            summary = admission
+                .take_summary()
+                .filter(|summary| summary.level().is_enabled_at(min_level));
+            if !admission.emit() {
+                if let (Some(tenant), Some(_)) = (&tenant, quota) {
+                    tenant.note_event_suppressed();
+                }
+                if let Some(summary) = summary {
+                    self.inner.lock().push(summary);
+                }
+                return;
+            }
+        }
+        let mut inner = self.inner.lock();
+        if let Some(summary) = summary {
+            inner.push(summary);
+        }
+        inner.push(entry);
+    }
+
+    /// Records every rate-limit summary that has come due by `now`, for keys
+    /// that went quiet after being suppressed. Returns how many were due.
+    pub fn flush_rate_limit_summaries(&self, now: Time) -> usize {
+        let Some(limiter) = &self.rate_limiter else {
+            return 0;
+        };
+        let summaries = limiter.take_due_summaries(now);
+        let due = summaries.len();
+        let min_level = self.min_level();
+        let mut inner = self.inner.lock();
+        for summary in summaries {
+            if summary.level().is_enabled_at(min_level) {
+                inner.push(summary);
+            }
         }
-        inner.entries.push_back(entry);
+        due
     }
 
     /// Drains all entries from the collector.
@@ -99,7 +241,7 @@ impl LogCollector {
     /// Returns the configured minimum level.
     #[must_use]
     pub fn min_level(&self) -> LogLevel {
-        self.inner.lock().min_level
+        level_from_rank(self.min_level.load(Ordering::Relaxed))
     }
 }
 
@@ -190,6 +190,21 @@ impl DiagnosticContext {
         })
     }
 
+    /// Returns a custom field of the current thread-local context without
+    /// cloning the whole context.
+    ///
+    /// Cheaper than `DiagnosticContext::current().custom(key)` on hot paths
+    /// such as histogram observations that look up an exemplar trace id.
+    #[must_use]
+    pub fn current_custom(key: &str) -> Option<String> {
+        CONTEXT_STACK.with(|stack| {
+            stack
+                .borrow()
+                .last()
+                .and_then(|entry| entry.context.custom.get(key).cloned())
+        })
+    }
+
     // Accessors
 
     /// Returns the task ID.
@@ -0,0 +1,1791 @@
+//! Typed metrics registry with Prometheus / OpenMetrics text exposition.
+//!
+//! [`Metrics`](super::Metrics) is an unlabeled, programmatic registry. This
+//! module adds what a scrape endpoint needs:
+//!
+//! - **Typed, labeled families**: [`CounterFamily`], [`GaugeFamily`] and
+//!   [`HistogramFamily`] registered by name on a [`MetricsRegistry`], with
+//!   per-family histogram bucket boundaries.
+//! - **Callback families** ([`MetricsRegistry::gauge_fn`],
+//!   [`MetricsRegistry::counter_fn`]) evaluated at scrape time, for state
+//!   that already lives elsewhere (runtime snapshots, listener stats).
+//! - **Cardinality guard**: every family caps its distinct label sets.
+//!   Label sets beyond the cap are folded into a single overflow series
+//!   whose label values are all [`OVERFLOW_LABEL_VALUE`], and the rejection
+//!   is counted in `asupersync_metrics_series_overflow_total{family=...}`,
+//!   so a labeling bug degrades resolution instead of exploding series count.
+//! - **Exposition**: [`ExpositionFormat::Prometheus`] (text format 0.0.4)
+//!   and [`ExpositionFormat::OpenMetrics`] (1.0.0, with histogram exemplars
+//!   carrying trace ids). Output is deterministic: families and series are
+//!   rendered in name / label order.
+//! - **Endpoints**: [`MetricsHandler`] mounts on the [`web`](crate::web)
+//!   router at `/metrics`; [`bind_metrics_listener`] is a minimal HTTP/1.1
+//!   listener for processes that do not otherwise run a server. Both accept
+//!   `?region=<name>` to scrape one region's series (see
+//!   [`tenant`](super::tenant)).
+//!
+//! # Consistency
+//!
+//! Each series is read atomically during a scrape: counters and gauges are
+//! single atomics, and a histogram's buckets, `_sum` and `_count` are copied
+//! under the series lock, so a scrape never shows a `+Inf` bucket that
+//! disagrees with `_count`.
+//!
+//! # Exemplars
+//!
+//! [`HistogramSeries::observe`] attaches the trace id found in the current
+//! [`DiagnosticContext`] custom field [`TRACE_ID_CONTEXT_FIELD`] (if any) as
+//! the exemplar of the bucket the value lands in. Use
+//! [`HistogramSeries::observe_with_exemplar`] to pass one explicitly.
+//! Exemplars are only rendered in the OpenMetrics format.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::observability::exposition::{MetricOpts, MetricsRegistry};
+//! use std::sync::Arc;
+//!
+//! let registry = Arc::new(MetricsRegistry::new());
+//! let requests = registry
+//!     .counter(MetricOpts::new("http_requests_total", "Requests served").labels(&["method"]))?;
+//! requests.with_labels(&["GET"])?.inc();
+//!
+//! let latency = registry.histogram(
+//!     MetricOpts::new("http_request_seconds", "Request latency"),
+//!     vec![0.005, 0.05, 0.5, 5.0],
+//! )?;
+//! latency.with_labels(&[])?.observe(0.012);
+//!
+//! let app = Router::new().route("/metrics", get(MetricsHandler::new(Arc::clone(&registry))));
+//! ```
+
+use super::context::DiagnosticContext;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::http::h1::types::{Method, Request as HttpRequest, Response as HttpResponse};
+use parking_lot::{Mutex, RwLock};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+
+/// Default cap on distinct label sets per family.
+pub const DEFAULT_MAX_SERIES_PER_FAMILY: usize = 1_000;
+
+/// Label value used for every label of the overflow series.
+pub const OVERFLOW_LABEL_VALUE: &str = "__overflow__";
+
+/// Label carrying the region name on region-scoped families; scrapes can be
+/// filtered on it.
+pub const REGION_LABEL: &str = "region";
+
+/// [`DiagnosticContext`] custom field consulted for histogram exemplars.
+pub const TRACE_ID_CONTEXT_FIELD: &str = "trace_id";
+
+/// Name of the self-metric that counts label sets folded into overflow.
+const OVERFLOW_FAMILY_NAME: &str = "asupersync_metrics_series_overflow";
+
+/// OpenMetrics limits the combined exemplar label set to 128 UTF-8
+/// characters; `trace_id=""` uses 10 of them.
+const MAX_EXEMPLAR_TRACE_ID_LEN: usize = 118;
+
+// ─── Errors ──────────────────────────────────────────────────────────────────
+
+/// Error returned by registration and label lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// Metric name does not match `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+    InvalidMetricName(String),
+    /// Label name is malformed, reserved (`__` prefix, `le` on histograms),
+    /// or duplicated.
+    InvalidLabelName(String),
+    /// Histogram bucket boundaries are not finite and strictly increasing.
+    InvalidBuckets(String),
+    /// A family with this name exists with a different type, label names,
+    /// or buckets.
+    AlreadyRegistered(String),
+    /// `with_labels` was called with the wrong number of values.
+    LabelCardinalityMismatch {
+        /// Family name.
+        family: String,
+        /// Number of label names the family declares.
+        expected: usize,
+        /// Number of values supplied.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMetricName(name) => write!(f, "invalid metric name `{name}`"),
+            Self::InvalidLabelName(name) => write!(f, "invalid label name `{name}`"),
+            Self::InvalidBuckets(reason) => write!(f, "invalid histogram buckets: {reason}"),
+            Self::AlreadyRegistered(name) => write!(
+                f,
+                "metric family `{name}` is already registered with a different shape"
+            ),
+            Self::LabelCardinalityMismatch {
+                family,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "metric family `{family}` expects {expected} label values, got {actual}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+// ─── Options ─────────────────────────────────────────────────────────────────
+
+/// Name, help text, label names, and series cap for a family.
+#[derive(Debug, Clone)]
+pub struct MetricOpts {
+    name: String,
+    help: String,
+    label_names: Vec<String>,
+    max_series: Option<usize>,
+}
+
+impl MetricOpts {
+    /// Creates options for an unlabeled family.
+    ///
+    /// Counter names may carry the conventional `_total` suffix; it is
+    /// stripped from the family name and re-added to the sample name.
+    #[must_use]
+    pub fn new(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            help: help.into(),
+            label_names: Vec::new(),
+            max_series: None,
+        }
+    }
+
+    /// Sets the label names, in the order `with_labels` expects values.
+    #[must_use]
+    pub fn labels(mut self, names: &[&str]) -> Self {
+        self.label_names = names.iter().map(|name| (*name).to_string()).collect();
+        self
+    }
+
+    /// Overrides the registry's per-family series cap for this family.
+    #[must_use]
+    pub fn max_series(mut self, cap: usize) -> Self {
+        self.max_series = Some(cap);
+        self
+    }
+
+    /// Prepends the [`REGION_LABEL`] label and, unless a cap was set, lifts
+    /// the family cap: region quotas bound these families instead, so one
+    /// region cannot exhaust a cap the others share.
+    pub(crate) fn region_scoped(mut self) -> Self {
+        self.label_names.insert(0, REGION_LABEL.to_string());
+        self.max_series.get_or_insert(usize::MAX);
+        self
+    }
+}
+
+/// Text exposition format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpositionFormat {
+    /// Prometheus text format 0.0.4.
+    Prometheus,
+    /// OpenMetrics text format 1.0.0 (with exemplars and `# EOF`).
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    /// The `Content-Type` header value for this format.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Prometheus => "text/plain; version=0.0.4; charset=utf-8",
+            Self::OpenMetrics => "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        }
+    }
+
+    /// Picks the format from a scrape request's `Accept` header.
+    ///
+    /// OpenMetrics is served only when explicitly accepted with a non-zero
+    /// quality; everything else gets the Prometheus text format.
+    #[must_use]
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Prometheus;
+        };
+        let accepts_openmetrics = accept.split(',').any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media = params.next().unwrap_or_default();
+            if !media.eq_ignore_ascii_case("application/openmetrics-text") {
+                return false;
+            }
+            params
+                .filter_map(|param| param.strip_prefix("q="))
+                .all(|q| !matches!(q.parse::<f64>(), Ok(q) if q <= 0.0))
+        });
+        if accepts_openmetrics {
+            Self::OpenMetrics
+        } else {
+            Self::Prometheus
+        }
+    }
+}
+
+// ─── Series ──────────────────────────────────────────────────────────────────
+
+mod sealed {
+    use std::sync::Arc;
+
+    pub trait Sealed {
+        fn create(buckets: &Arc<[f64]>) -> Self;
+    }
+}
+
+/// A series type a [`Family`] can hold. Sealed.
+pub trait Series: sealed::Sealed + Send + Sync + 'static {}
+
+/// One labeled counter series.
+#[derive(Debug, Default)]
+pub struct CounterSeries {
+    value: AtomicU64,
+}
+
+impl CounterSeries {
+    /// Increments by 1.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Increments by `value`.
+    pub fn inc_by(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Returns the current value.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+impl sealed::Sealed for CounterSeries {
+    fn create(_buckets: &Arc<[f64]>) -> Self {
+        Self::default()
+    }
+}
+
+impl Series for CounterSeries {}
+
+/// One labeled gauge series.
+#[derive(Debug, Default)]
+pub struct GaugeSeries {
+    value: AtomicI64,
+}
+
+impl GaugeSeries {
+    /// Sets the value.
+    pub fn set(&self, value: i64) {
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    /// Increments by 1.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Decrements by 1.
+    pub fn dec(&self) {
+        self.add(-1);
+    }
+
+    /// Adds `value` (which may be negative).
+    pub fn add(&self, value: i64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Returns the current value.
+    #[must_use]
+    pub fn get(&self) -> i64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+impl sealed::Sealed for GaugeSeries {
+    fn create(_buckets: &Arc<[f64]>) -> Self {
+        Self::default()
+    }
+}
+
+impl Series for GaugeSeries {}
+
+/// A trace-id exemplar attached to a histogram bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemplar {
+    /// Trace id of the observation.
+    pub trace_id: String,
+    /// Observed value.
+    pub value: f64,
+}
+
+#[derive(Debug)]
+struct HistogramState {
+    /// Non-cumulative per-bucket counts; the last slot is `+Inf`.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+    /// Most recent exemplar per bucket.
+    exemplars: Vec<Option<Exemplar>>,
+}
+
+/// One labeled histogram series.
+#[derive(Debug)]
+pub struct HistogramSeries {
+    buckets: Arc<[f64]>,
+    state: Mutex<HistogramState>,
+}
+
+/// Coherent point-in-time copy of a [`HistogramSeries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSeriesSnapshot {
+    /// Upper bounds of the finite buckets.
+    pub buckets: Vec<f64>,
+    /// Cumulative counts, one per finite bucket plus `+Inf`.
+    pub cumulative_counts: Vec<u64>,
+    /// Sum of observed values.
+    pub sum: f64,
+    /// Number of observations.
+    pub count: u64,
+    /// Most recent exemplar per bucket (same indexing as `cumulative_counts`).
+    pub exemplars: Vec<Option<Exemplar>>,
+}
+
+impl HistogramSeries {
+    /// Records `value`, attaching the current context's trace id (if any)
+    /// as the bucket exemplar. `NaN` is ignored.
+    pub fn observe(&self, value: f64) {
+        let trace_id = DiagnosticContext::current_custom(TRACE_ID_CONTEXT_FIELD);
+        self.record(value, trace_id);
+    }
+
+    /// Records `value` with an explicit exemplar trace id.
+    pub fn observe_with_exemplar(&self, value: f64, trace_id: &str) {
+        self.record(value, Some(trace_id.to_string()));
+    }
+
+    fn record(&self, value: f64, trace_id: Option<String>) {
+        if value.is_nan() {
+            return;
+        }
+        let index = self.buckets.partition_point(|bound| *bound < value);
+        let exemplar = trace_id
+            .filter(|id| !id.is_empty() && id.chars().count() <= MAX_EXEMPLAR_TRACE_ID_LEN)
+            .map(|trace_id| Exemplar { trace_id, value });
+        let mut state = self.state.lock();
+        state.counts[index] += 1;
+        state.sum += value;
+        state.count += 1;
+        if exemplar.is_some() {
+            state.exemplars[index] = exemplar;
+        }
+    }
+
+    /// Returns a coherent snapshot (buckets, sum and count read together).
+    #[must_use]
+    pub fn snapshot(&self) -> HistogramSeriesSnapshot {
+        let state = self.state.lock();
+        let mut cumulative = 0;
+        let cumulative_counts = state
+            .counts
+            .iter()
+            .map(|count| {
+                cumulative += count;
+                cumulative
+            })
+            .collect();
+        HistogramSeriesSnapshot {
+            buckets: self.buckets.to_vec(),
+            cumulative_counts,
+            sum: state.sum,
+            count: state.count,
+            exemplars: state.exemplars.clone(),
+        }
+    }
+}
+
+impl sealed::Sealed for HistogramSeries {
+    fn create(buckets: &Arc<[f64]>) -> Self {
+        let slots = buckets.len() + 1;
+        Self {
+            buckets: Arc::clone(buckets),
+            state: Mutex::new(HistogramState {
+                counts: vec![0; slots],
+                sum: 0.0,
+                count: 0,
+                exemplars: vec![None; slots],
+            }),
+        }
+    }
+}
+
+impl Series for HistogramSeries {}
+
+// ─── Families ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+struct SeriesMap<S> {
+    series: BTreeMap<Vec<String>, Arc<S>>,
+    overflow: Option<Arc<S>>,
+}
+
+/// A named, typed metric family keyed by label values.
+#[derive(Debug)]
+pub struct Family<S: Series> {
+    name: String,
+    help: String,
+    label_names: Vec<String>,
+    buckets: Arc<[f64]>,
+    max_series: usize,
+    series: Mutex<SeriesMap<S>>,
+    overflow_rejections: AtomicU64,
+    overflow_warned: AtomicBool,
+}
+
+/// Counter family.
+pub type CounterFamily = Family<CounterSeries>;
+/// Gauge family.
+pub type GaugeFamily = Family<GaugeSeries>;
+/// Histogram family.
+pub type HistogramFamily = Family<HistogramSeries>;
+
+impl<S: Series> Family<S> {
+    fn new(opts: &MetricOpts, name: String, buckets: Arc<[f64]>, max_series: usize) -> Self {
+        let family = Self {
+            name,
+            help: opts.help.clone(),
+            label_names: opts.label_names.clone(),
+            buckets,
+            max_series,
+            series: Mutex::new(SeriesMap {
+                series: BTreeMap::new(),
+                overflow: None,
+            }),
+            overflow_rejections: AtomicU64::new(0),
+            overflow_warned: AtomicBool::new(false),
+        };
+        // Unlabeled families expose their single series (at zero) from the
+        // first scrape, like every Prometheus client library.
+        if family.label_names.is_empty() {
+            let series = Arc::new(S::create(&family.buckets));
+            family.series.lock().series.insert(Vec::new(), series);
+        }
+        family
+    }
+
+    /// Family name (without the counter `_total` suffix).
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Declared label names.
+    #[must_use]
+    pub fn label_names(&self) -> &[String] {
+        &self.label_names
+    }
+
+    /// Returns the series for `values`, creating it if needed.
+    ///
+    /// Once the family holds its cap of distinct label sets, new label sets
+    /// share the overflow series and bump [`Self::overflow_rejections`].
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::LabelCardinalityMismatch`] if `values` does not
+    /// match the declared label names.
+    pub fn with_labels(&self, values: &[&str]) -> Result<Arc<S>, RegistryError> {
+        if values.len() != self.label_names.len() {
+            return Err(RegistryError::LabelCardinalityMismatch {
+                family: self.name.clone(),
+                expected: self.label_names.len(),
+                actual: values.len(),
+            });
+        }
+        let key: Vec<String> = values.iter().map(|value| (*value).to_string()).collect();
+        let mut map = self.series.lock();
+        if let Some(series) = map.series.get(&key) {
+            return Ok(Arc::clone(series));
+        }
+        if map.series.len() >= self.max_series {
+            self.overflow_rejections.fetch_add(1, Ordering::Relaxed);
+            if !self.overflow_warned.swap(true, Ordering::Relaxed) {
+                crate::tracing_compat::warn!(
+                    "metrics: family '{}' reached its series cap ({}); \
+                     new label sets are folded into the '{}' series",
+                    self.name,
+                    self.max_series,
+                    OVERFLOW_LABEL_VALUE
+                );
+            }
+            let buckets = &self.buckets;
+            return Ok(Arc::clone(
+                map.overflow
+                    .get_or_insert_with(|| Arc::new(S::create(buckets))),
+            ));
+        }
+        let series = Arc::new(S::create(&self.buckets));
+        map.series.insert(key, Arc::clone(&series));
+        Ok(series)
+    }
+
+    /// Number of distinct label sets held, excluding the overflow series.
+    #[must_use]
+    pub fn series_count(&self) -> usize {
+        self.series.lock().series.len()
+    }
+
+    /// Number of label-set lookups folded into the overflow series.
+    #[must_use]
+    pub fn overflow_rejections(&self) -> u64 {
+        self.overflow_rejections.load(Ordering::Relaxed)
+    }
+
+    /// Series in label order, overflow last. Clones the `Arc`s so values are
+    /// read outside the family lock.
+    fn collect(&self) -> Vec<(Vec<String>, Arc<S>)> {
+        let map = self.series.lock();
+        let mut out: Vec<_> = map
+            .series
+            .iter()
+            .map(|(labels, series)| (labels.clone(), Arc::clone(series)))
+            .collect();
+        if let Some(overflow) = &map.overflow {
+            out.push((
+                vec![OVERFLOW_LABEL_VALUE.to_string(); self.label_names.len()],
+                Arc::clone(overflow),
+            ));
+        }
+        out
+    }
+}
+
+type CallbackFn<T> = Box<dyn Fn() -> Vec<(Vec<String>, T)> + Send + Sync>;
+
+struct CallbackFamily<T> {
+    name: String,
+    help: String,
+    label_names: Vec<String>,
+    max_series: usize,
+    callback: CallbackFn<T>,
+    overflow_rejections: AtomicU64,
+}
+
+impl<T> fmt::Debug for CallbackFamily<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackFamily")
+            .field("name", &self.name)
+            .field("label_names", &self.label_names)
+            .field("max_series", &self.max_series)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> CallbackFamily<T> {
+    /// Evaluates the callback, dropping samples with the wrong label arity
+    /// and truncating (in label order) beyond the series cap.
+    fn collect(&self) -> Vec<(Vec<String>, T)> {
+        let arity = self.label_names.len();
+        let mut samples: Vec<_> = (self.callback)()
+            .into_iter()
+            .filter(|(labels, _)| labels.len() == arity)
+            .collect();
+        samples.sort_by(|a, b| a.0.cmp(&b.0));
+        if samples.len() > self.max_series {
+            let dropped = samples.len() - self.max_series;
+            self.overflow_rejections
+                .fetch_add(dropped as u64, Ordering::Relaxed);
+            samples.truncate(self.max_series);
+        }
+        samples
+    }
+}
+
+#[derive(Debug, Clone)]
+enum RegisteredFamily {
+    Counter(Arc<CounterFamily>),
+    Gauge(Arc<GaugeFamily>),
+    Histogram(Arc<HistogramFamily>),
+    CounterFn(Arc<CallbackFamily<u64>>),
+    GaugeFn(Arc<CallbackFamily<i64>>),
+}
+
+impl RegisteredFamily {
+    fn overflow_rejections(&self) -> u64 {
+        match self {
+            Self::Counter(family) => family.overflow_rejections(),
+            Self::Gauge(family) => family.overflow_rejections(),
+            Self::Histogram(family) => family.overflow_rejections(),
+            Self::CounterFn(family) => family.overflow_rejections.load(Ordering::Relaxed),
+            Self::GaugeFn(family) => family.overflow_rejections.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// ─── Registry ────────────────────────────────────────────────────────────────
+
+/// Registry of typed metric families rendered on demand.
+#[derive(Debug)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<String, RegisteredFamily>>,
+    max_series_per_family: usize,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::with_max_series_per_family(DEFAULT_MAX_SERIES_PER_FAMILY)
+    }
+}
+
+impl MetricsRegistry {
+    /// Creates a registry with [`DEFAULT_MAX_SERIES_PER_FAMILY`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry whose families default to `cap` label sets each
+    /// (clamped to at least 1).
+    #[must_use]
+    pub fn with_max_series_per_family(cap: usize) -> Self {
+        Self {
+            families: RwLock::new(BTreeMap::new()),
+            max_series_per_family: cap.max(1),
+        }
+    }
+
+    /// Registers (or returns the identical existing) counter family.
+    ///
+    /// # Errors
+    ///
+    /// Invalid names, or a conflicting family under the same name.
+    pub fn counter(&self, opts: MetricOpts) -> Result<Arc<CounterFamily>, RegistryError> {
+        let name = counter_base_name(&opts.name);
+        validate(&name, &opts.label_names, false)?;
+        self.register(
+            &name,
+            &opts,
+            Arc::from(Vec::new()),
+            |existing| match existing {
+                RegisteredFamily::Counter(family) => Some(Arc::clone(family)),
+                _ => None,
+            },
+            RegisteredFamily::Counter,
+        )
+    }
+
+    /// Registers (or returns the identical existing) gauge family.
+    ///
+    /// # Errors
+    ///
+    /// Invalid names, or a conflicting family under the same name.
+    pub fn gauge(&self, opts: MetricOpts) -> Result<Arc<GaugeFamily>, RegistryError> {
+        let name = opts.name.clone();
+        validate(&name, &opts.label_names, false)?;
+        self.register(
+            &name,
+            &opts,
+            Arc::from(Vec::new()),
+            |existing| match existing {
+                RegisteredFamily::Gauge(family) => Some(Arc::clone(family)),
+                _ => None,
+            },
+            RegisteredFamily::Gauge,
+        )
+    }
+
+    /// Registers (or returns the identical existing) histogram family with
+    /// the given finite, strictly increasing bucket upper bounds. A trailing
+    /// `+Inf` bound is implied and may be omitted.
+    ///
+    /// # Errors
+    ///
+    /// Invalid names or buckets, or a conflicting family under the same name.
+    pub fn histogram(
+        &self,
+        opts: MetricOpts,
+        buckets: Vec<f64>,
+    ) -> Result<Arc<HistogramFamily>, RegistryError> {
+        let name = opts.name.clone();
+        validate(&name, &opts.label_names, true)?;
+        let buckets = validate_buckets(buckets)?;
+        self.register(
+            &name,
+            &opts,
+            buckets,
+            |existing| match existing {
+                RegisteredFamily::Histogram(family) => Some(Arc::clone(family)),
+                _ => None,
+            },
+            RegisteredFamily::Histogram,
+        )
+    }
+
+    /// Registers a counter family whose samples are produced by `callback`
+    /// at scrape time. Each sample is `(label values, value)`.
+    ///
+    /// # Errors
+    ///
+    /// Invalid names, or any existing family under the same name.
+    pub fn counter_fn<F>(&self, opts: MetricOpts, callback: F) -> Result<(), RegistryError>
+    where
+        F: Fn() -> Vec<(Vec<String>, u64)> + Send + Sync + 'static,
+    {
+        let name = counter_base_name(&opts.name);
+        validate(&name, &opts.label_names, false)?;
+        let family = self.callback_family(name.clone(), &opts, Box::new(callback));
+        self.insert_new(name, RegisteredFamily::CounterFn(Arc::new(family)))
+    }
+
+    /// Registers a gauge family whose samples are produced by `callback` at
+    /// scrape time. Each sample is `(label values, value)`.
+    ///
+    /// # Errors
+    ///
+    /// Invalid names, or any existing family under the same name.
+    pub fn gauge_fn<F>(&self, opts: MetricOpts, callback: F) -> Result<(), RegistryError>
+    where
+        F: Fn() -> Vec<(Vec<String>, i64)> + Send + Sync + 'static,
+    {
+        let name = opts.name.clone();
+        validate(&name, &opts.label_names, false)?;
+        let family = self.callback_family(name.clone(), &opts, Box::new(callback));
+        self.insert_new(name, RegisteredFamily::GaugeFn(Arc::new(family)))
+    }
+
+    /// Registers the runtime's region / task / obligation counts as the
+    /// gauge `asupersync_runtime_entities{kind=...}`, read from `snapshot`
+    /// once per scrape.
+    ///
+    /// # Errors
+    ///
+    /// The family name is already taken.
+    pub fn register_runtime_snapshot<F>(&self, snapshot: F) -> Result<(), RegistryError>
+    where
+        F: Fn() -> crate::runtime::RuntimeSnapshot + Send + Sync + 'static,
+    {
+        self.gauge_fn(
+            MetricOpts::new(
+                "asupersync_runtime_entities",
+                "Live runtime entities by kind.",
+            )
+            .labels(&["kind"]),
+            move || {
+                let snapshot = snapshot();
+                [
+                    ("obligation", snapshot.obligations.len()),
+                    ("region", snapshot.regions.len()),
+                    ("task", snapshot.tasks.len()),
+                ]
+                .into_iter()
+                .map(|(kind, count)| {
+                    (
+                        vec![kind.to_string()],
+                        i64::try_from(count).unwrap_or(i64::MAX),
+                    )
+                })
+                .collect()
+            },
+        )
+    }
+
+    /// Registered family names in render order.
+    #[must_use]
+    pub fn family_names(&self) -> Vec<String> {
+        self.families.read().keys().cloned().collect()
+    }
+
+    /// Renders every family in `format`.
+    #[must_use]
+    pub fn render(&self, format: ExpositionFormat) -> String {
+        self.render_filtered(format, None)
+    }
+
+    /// Renders only the series labeled `region="<region>"`, as registered
+    /// through a [`RegionScope`](super::tenant::RegionScope). Families
+    /// without a [`REGION_LABEL`] label are omitted.
+    #[must_use]
+    pub fn render_region(&self, format: ExpositionFormat, region: &str) -> String {
+        self.render_filtered(format, Some(region))
+    }
+
+    fn render_filtered(&self, format: ExpositionFormat, region: Option<&str>) -> String {
+        let families: Vec<(String, RegisteredFamily)> = self
+            .families
+            .read()
+            .iter()
+            .map(|(name, family)| (name.clone(), family.clone()))
+            .collect();
+
+        let mut out = String::new();
+        let mut overflowed = Vec::new();
+        for (name, family) in &families {
+            match family {
+                RegisteredFamily::Counter(family) => {
+                    let mut samples = family
+                        .collect()
+                        .into_iter()
+                        .map(|(labels, series)| (labels, series.get()))
+                        .collect::<Vec<_>>();
+                    if !retain_region(&mut samples, &family.label_names, region) {
+                        continue;
+                    }
+                    render_counter(
+                        &mut out,
+                        format,
+                        name,
+                        &family.help,
+                        &family.label_names,
+                        &samples,
+                    );
+                }
+                RegisteredFamily::CounterFn(family) => {
+                    let mut samples = family.collect();
+                    if !retain_region(&mut samples, &family.label_names, region) {
+                        continue;
+                    }
+                    render_counter(
+                        &mut out,
+                        format,
+                        name,
+                        &family.help,
+                        &family.label_names,
+                        &samples,
+                    );
+                }
+                RegisteredFamily::Gauge(family) => {
+                    let mut samples = family
+                        .collect()
+                        .into_iter()
+                        .map(|(labels, series)| (labels, series.get()))
+                        .collect::<Vec<_>>();
+                    if !retain_region(&mut samples, &family.label_names, region) {
+                        continue;
+                    }
+                    render_gauge(
+                        &mut out,
+                        format,
+                        name,
+                        &family.help,
+                        &family.label_names,
+                        &samples,
+                    );
+                }
+                RegisteredFamily::GaugeFn(family) => {
+                    let mut samples = family.collect();
+                    if !retain_region(&mut samples, &family.label_names, region) {
+                        continue;
+                    }
+                    render_gauge(
+                        &mut out,
+                        format,
+                        name,
+                        &family.help,
+                        &family.label_names,
+                        &samples,
+                    );
+                }
+                RegisteredFamily::Histogram(family) => {
+                    let mut samples = family
+                        .collect()
+                        .into_iter()
+                        .map(|(labels, series)| (labels, series.snapshot()))
+                        .collect::<Vec<_>>();
+                    if !retain_region(&mut samples, &family.label_names, region) {
+                        continue;
+                    }
+                    render_histogram(
+                        &mut out,
+                        format,
+                        name,
+                        &family.help,
+                        &family.label_names,
+                        &samples,
+                    );
+                }
+            }
+            let rejections = family.overflow_rejections();
+            if rejections > 0 {
+                overflowed.push((vec![name.clone()], rejections));
+            }
+        }
+        if region.is_none() && !overflowed.is_empty() {
+            render_counter(
+                &mut out,
+                format,
+                OVERFLOW_FAMILY_NAME,
+                "Label sets folded into a family's overflow series by the cardinality cap.",
+                &["family".to_string()],
+                &overflowed,
+            );
+        }
+        if format == ExpositionFormat::OpenMetrics {
+            out.push_str("# EOF\n");
+        }
+        out
+    }
+
+    /// Renders in the Prometheus text format.
+    #[must_use]
+    pub fn render_prometheus(&self) -> String {
+        self.render(ExpositionFormat::Prometheus)
+    }
+
+    /// Renders in the OpenMetrics text format.
+    #[must_use]
+    pub fn render_openmetrics(&self) -> String {
+        self.render(ExpositionFormat::OpenMetrics)
+    }
+
+    fn register<S: Series>(
+        &self,
+        name: &str,
+        opts: &MetricOpts,
+        buckets: Arc<[f64]>,
+        existing: impl Fn(&RegisteredFamily) -> Option<Arc<Family<S>>>,
+        wrap: impl Fn(Arc<Family<S>>) -> RegisteredFamily,
+    ) -> Result<Arc<Family<S>>, RegistryError> {
+        let mut families = self.families.write();
+        if let Some(registered) = families.get(name) {
+            return existing(registered)
+                .filter(|family| {
+                    family.label_names == opts.label_names && family.buckets == buckets
+                })
+                .ok_or_else(|| RegistryError::AlreadyRegistered(name.to_string()));
+        }
+        let max_series = opts.max_series.unwrap_or(self.max_series_per_family).max(1);
+        let family = Arc::new(Family::new(opts, name.to_string(), buckets, max_series));
+        families.insert(name.to_string(), wrap(Arc::clone(&family)));
+        Ok(family)
+    }
+
+    fn callback_family<T>(
+        &self,
+        name: String,
+        opts: &MetricOpts,
+        callback: CallbackFn<T>,
+    ) -> CallbackFamily<T> {
+        CallbackFamily {
+            name,
+            help: opts.help.clone(),
+            label_names: opts.label_names.clone(),
+            max_series: opts.max_series.unwrap_or(self.max_series_per_family).max(1),
+            callback,
+            overflow_rejections: AtomicU64::new(0),
+        }
+    }
+
+    fn insert_new(&self, name: String, family: RegisteredFamily) -> Result<(), RegistryError> {
+        let mut families = self.families.write();
+        if families.contains_key(&name) {
+            return Err(RegistryError::AlreadyRegistered(name));
+        }
+        families.insert(name, family);
+        Ok(())
+    }
+}
+
+// ─── Validation ──────────────────────────────────────────────────────────────
+
+fn counter_base_name(name: &str) -> String {
+    name.strip_suffix("_total").unwrap_or(name).to_string()
+}
+
+fn is_valid_metric_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_' || b == b':')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b':')
+}
+
+fn is_valid_label_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    !name.starts_with("__")
+        && bytes
+            .next()
+            .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+fn validate(name: &str, label_names: &[String], histogram: bool) -> Result<(), RegistryError> {
+    if !is_valid_metric_name(name) || name == OVERFLOW_FAMILY_NAME {
+        return Err(RegistryError::InvalidMetricName(name.to_string()));
+    }
+    for (i, label) in label_names.iter().enumerate() {
+        let reserved = histogram && label == "le";
+        if !is_valid_label_name(label) || reserved || label_names[..i].contains(label) {
+            return Err(RegistryError::InvalidLabelName(label.clone()));
+        }
+    }
+    Ok(())
+}
+
+fn validate_buckets(mut buckets: Vec<f64>) -> Result<Arc<[f64]>, RegistryError> {
+    if buckets.last() == Some(&f64::INFINITY) {
+        buckets.pop();
+    }
+    if buckets.iter().any(|bound| !bound.is_finite()) {
+        return Err(RegistryError::InvalidBuckets(
+            "bounds must be finite".to_string(),
+        ));
+    }
+    if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(RegistryError::InvalidBuckets(
+            "bounds must be strictly increasing".to_string(),
+        ));
+    }
+    Ok(Arc::from(buckets))
+}
+
+// ─── Rendering ───────────────────────────────────────────────────────────────
+
+/// Keeps the samples whose [`REGION_LABEL`] value is `region`. Returns
+/// `false` when the family has nothing to show under the filter.
+fn retain_region<T>(
+    samples: &mut Vec<(Vec<String>, T)>,
+    label_names: &[String],
+    region: Option<&str>,
+) -> bool {
+    let Some(region) = region else {
+        return true;
+    };
+    let Some(index) = label_names.iter().position(|name| name == REGION_LABEL) else {
+        return false;
+    };
+    samples.retain(|(labels, _)| labels.get(index).is_some_and(|value| value == region));
+    !samples.is_empty()
+}
+
+fn render_header(out: &mut String, format: ExpositionFormat, name: &str, kind: &str, help: &str) {
+    if !help.is_empty() {
+        let _ = writeln!(out, "# HELP {name} {}", escape_help(help, format));
+    }
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn render_counter(
+    out: &mut String,
+    format: ExpositionFormat,
+    name: &str,
+    help: &str,
+    label_names: &[String],
+    samples: &[(Vec<String>, u64)],
+) {
+    // Prometheus 0.0.4 names the family after its sample (`x_total`);
+    // OpenMetrics names the family `x` and suffixes the sample.
+    let sample_name = format!("{name}_total");
+    let family_name = match format {
+        ExpositionFormat::Prometheus => sample_name.as_str(),
+        ExpositionFormat::OpenMetrics => name,
+    };
+    render_header(out, format, family_name, "counter", help);
+    for (labels, value) in samples {
+        out.push_str(&sample_name);
+        write_labels(out, label_names, labels, None);
+        let _ = writeln!(out, " {value}");
+    }
+}
+
+fn render_gauge(
+    out: &mut String,
+    format: ExpositionFormat,
+    name: &str,
+    help: &str,
+    label_names: &[String],
+    samples: &[(Vec<String>, i64)],
+) {
+    render_header(out, format, name, "gauge", help);
+    for (labels, value) in samples {
+        out.push_str(name);
+        write_labels(out, label_names, labels, None);
+        let _ = writeln!(out, " {value}");
+    }
+}
+
+fn render_histogram(
+    out: &mut String,
+    format: ExpositionFormat,
+    name: &str,
+    help: &str,
+    label_names: &[String],
+    samples: &[(Vec<String>, HistogramSeriesSnapshot)],
+) {
+    render_header(out, format, name, "histogram", help);
+    for (labels, snapshot) in samples {
+        for (i, cumulative) in snapshot.cumulative_counts.iter().enumerate() {
+            let le = snapshot
+                .buckets
+                .get(i)
+                .map_or_else(|| "+Inf".to_string(), |bound| format_float(*bound, format));
+            let _ = write!(out, "{name}_bucket");
+            write_labels(out, label_names, labels, Some(&le));
+            let _ = write!(out, " {cumulative}");
+            if format == ExpositionFormat::OpenMetrics
+                && let Some(Some(exemplar)) = snapshot.exemplars.get(i)
+            {
+                let _ = write!(
+                    out,
+                    " # {{trace_id=\"{}\"}} {}",
+                    escape_label_value(&exemplar.trace_id),
+                    format_float(exemplar.value, format)
+                );
+            }
+            out.push('\n');
+        }
+        let _ = write!(out, "{name}_sum");
+        write_labels(out, label_names, labels, None);
+        let _ = writeln!(out, " {}", format_float(snapshot.sum, format));
+        let _ = write!(out, "{name}_count");
+        write_labels(out, label_names, labels, None);
+        let _ = writeln!(out, " {}", snapshot.count);
+    }
+}
+
+fn write_labels(out: &mut String, names: &[String], values: &[String], le: Option<&str>) {
+    if names.is_empty() && le.is_none() {
+        return;
+    }
+    out.push('{');
+    let mut first = true;
+    for (name, value) in names.iter().zip(values) {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        let _ = write!(out, "{name}=\"{}\"", escape_label_value(value));
+    }
+    if let Some(le) = le {
+        if !first {
+            out.push(',');
+        }
+        let _ = write!(out, "le=\"{le}\"");
+    }
+    out.push('}');
+}
+
+/// Formats a float so both grammars accept it. OpenMetrics requires integral
+/// bucket bounds to carry a fractional part (`1.0`, not `1`).
+fn format_float(value: f64, format: ExpositionFormat) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        let inf = if value > 0.0 { "+Inf" } else { "-Inf" };
+        inf.to_string()
+    } else if format == ExpositionFormat::OpenMetrics && value.fract() == 0.0 && value.abs() < 1e15
+    {
+        format!("{value:.1}")
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Escapes a label value with exactly the escapes both grammars define
+/// (`\\`, `\"`, `\n`). Other control characters have no escape in the
+/// grammar and are replaced with U+FFFD so a hostile value cannot split
+/// lines in downstream log or terminal readers.
+fn escape_label_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str(r"\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str(r"\n"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => out.push('\u{FFFD}'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes HELP text: `\\` and `\n` in both formats, plus `\"` in
+/// OpenMetrics. Other control characters are replaced as in label values.
+fn escape_help(help: &str, format: ExpositionFormat) -> String {
+    let mut out = String::with_capacity(help.len());
+    for c in help.chars() {
+        match c {
+            '\\' => out.push_str(r"\\"),
+            '\n' => out.push_str(r"\n"),
+            '"' if format == ExpositionFormat::OpenMetrics => out.push_str("\\\""),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => out.push('\u{FFFD}'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ─── Endpoints ───────────────────────────────────────────────────────────────
+
+/// [`web`](crate::web) handler serving a registry, typically at `/metrics`.
+///
+/// The format is negotiated from the `Accept` header
+/// ([`ExpositionFormat::negotiate`]).
+#[derive(Debug, Clone)]
+pub struct MetricsHandler {
+    registry: Arc<MetricsRegistry>,
+}
+
+impl MetricsHandler {
+    /// Creates a handler for `registry`.
+    #[must_use]
+    pub fn new(registry: Arc<MetricsRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl crate::web::handler::Handler for MetricsHandler {
+    fn call(
+        &self,
+        _cx: &crate::cx::Cx,
+        req: crate::web::extract::Request,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = crate::web::response::Response> + Send + '_>,
+    > {
+        let format = ExpositionFormat::negotiate(req.header("accept"));
+        let body = match region_filter(req.query.as_deref()) {
+            Some(region) => self.registry.render_region(format, region),
+            None => self.registry.render(format),
+        };
+        let response = crate::web::response::Response::new(
+            crate::web::response::StatusCode::OK,
+            body.into_bytes(),
+        )
+        .header("content-type", format.content_type());
+        Box::pin(std::future::ready(response))
+    }
+}
+
+/// Serves one scrape request on the [`bind_metrics_listener`] listener.
+#[cfg(not(target_arch = "wasm32"))]
+fn scrape_response(registry: &MetricsRegistry, request: &HttpRequest) -> HttpResponse {
+    let (path, query) = match request.uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (request.uri.as_str(), None),
+    };
+    if path != "/metrics" {
+        return HttpResponse::new(404, "Not Found", Vec::new());
+    }
+    if !matches!(request.method, Method::Get | Method::Head) {
+        return HttpResponse::new(405, "Method Not Allowed", Vec::new())
+            .with_header("allow", "GET, HEAD");
+    }
+    let format = ExpositionFormat::negotiate(request.header_value("accept"));
+    let body = match region_filter(query) {
+        Some(region) => registry.render_region(format, region),
+        None => registry.render(format),
+    };
+    HttpResponse::new(200, "OK", body.into_bytes())
+        .with_header("content-type", format.content_type())
+}
+
+/// Returns the `region` query parameter of a scrape, if non-empty.
+fn region_filter(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("region="))
+        .filter(|region| !region.is_empty())
+}
+
+/// Binds a minimal HTTP/1.1 listener serving `registry` at `GET /metrics`
+/// for processes that do not otherwise run a server.
+///
+/// Every other path gets 404. Host-header validation is disabled (the
+/// endpoint emits no URLs, and scrapers address it by IP). Drive it with
+/// [`Http1Listener::run`](crate::http::h1::listener::Http1Listener::run) and
+/// stop it through its shutdown signal, like any other listener.
+///
+/// # Errors
+///
+/// Binding the socket failed.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn bind_metrics_listener<A>(
+    addr: A,
+    registry: Arc<MetricsRegistry>,
+) -> std::io::Result<
+    crate::http::h1::listener::Http1Listener<
+        impl Fn(HttpRequest) -> std::future::Ready<HttpResponse> + Send + Sync + 'static,
+    >,
+>
+where
+    A: std::net::ToSocketAddrs + Send + 'static,
+{
+    use crate::http::h1::listener::{Http1Listener, Http1ListenerConfig};
+    use crate::http::h1::server::{HostPolicy, Http1Config};
+
+    let config = Http1ListenerConfig::default()
+        .http_config(Http1Config::default().host_policy(HostPolicy::allow_all()));
+    Http1Listener::bind_with_config(
+        addr,
+        move |request: HttpRequest| std::future::ready(scrape_response(&registry, &request)),
+        config,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line-level check of the text exposition grammar shared by both
+    /// formats: comments are `# HELP` / `# TYPE` (or `# EOF` last in
+    /// OpenMetrics), samples are `name{labels} value [# exemplar]`.
+    fn assert_grammar(text: &str, format: ExpositionFormat) {
+        assert!(text.ends_with('\n'), "exposition must end with a newline");
+        let lines: Vec<&str> = text.lines().collect();
+        if format == ExpositionFormat::OpenMetrics {
+            assert_eq!(
+                lines.last(),
+                Some(&"# EOF"),
+                "OpenMetrics must end with # EOF"
+            );
+        }
+        let mut declared = std::collections::BTreeSet::new();
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                if comment == "EOF" {
+                    assert_eq!(format, ExpositionFormat::OpenMetrics);
+                    assert_eq!(i, lines.len() - 1, "# EOF must be the last line");
+                    continue;
+                }
+                let mut parts = comment.splitn(3, ' ');
+                let keyword = parts.next().unwrap();
+                let name = parts.next().expect("comment names a family");
+                assert!(is_valid_metric_name(name), "bad family name in {line:?}");
+                match keyword {
+                    "HELP" => {}
+                    "TYPE" => {
+                        let kind = parts.next().expect("TYPE has a kind");
+                        assert!(
+                            ["counter", "gauge", "histogram"].contains(&kind),
+                            "unknown type in {line:?}"
+                        );
+                        assert!(declared.insert(name.to_string()), "family declared twice");
+                    }
+                    other => panic!("unexpected comment keyword {other:?}"),
+                }
+                continue;
+            }
+            let (sample, exemplar) = match line.split_once(" # ") {
+                Some((sample, exemplar)) => (sample, Some(exemplar)),
+                None => (*line, None),
+            };
+            if let Some(exemplar) = exemplar {
+                assert_eq!(format, ExpositionFormat::OpenMetrics);
+                assert!(sample.contains("_bucket{"), "exemplars only on buckets");
+                let (labels, value) = exemplar.rsplit_once(' ').unwrap();
+                assert!(labels.starts_with("{trace_id=\"") && labels.ends_with("\"}"));
+                value.parse::<f64>().expect("exemplar value is a float");
+            }
+            let name_end = sample
+                .find(['{', ' '])
+                .unwrap_or_else(|| panic!("sample without value: {line:?}"));
+            let name = &sample[..name_end];
+            assert!(is_valid_metric_name(name), "bad sample name in {line:?}");
+            let rest = &sample[name_end..];
+            let value = if let Some(labels) = rest.strip_prefix('{') {
+                let close = labels.rfind('}').expect("label set closes");
+                assert_label_set(&labels[..close], line);
+                labels[close + 1..].trim_start()
+            } else {
+                rest.trim_start()
+            };
+            assert!(
+                value == "+Inf"
+                    || value == "-Inf"
+                    || value == "NaN"
+                    || value.parse::<f64>().is_ok(),
+                "bad sample value in {line:?}"
+            );
+            assert!(
+                declared
+                    .iter()
+                    .any(|family| name.starts_with(family.as_str())),
+                "sample {name} precedes its TYPE line"
+            );
+        }
+    }
+
+    fn assert_label_set(labels: &str, line: &str) {
+        let mut rest = labels;
+        while !rest.is_empty() {
+            let eq = rest
+                .find("=\"")
+                .unwrap_or_else(|| panic!("bad label in {line:?}"));
+            assert!(is_valid_label_name(&rest[..eq]) || &rest[..eq] == "le");
+            let mut chars = rest[eq + 2..].char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        let (_, escaped) = chars.next().expect("escape has a char");
+                        assert!(
+                            matches!(escaped, '\\' | '"' | 'n'),
+                            "invalid escape in {line:?}"
+                        );
+                    }
+                    Some((i, '"')) => break eq + 2 + i,
+                    Some((_, c)) => assert!(c != '\n', "raw newline in label value"),
+                    None => panic!("unterminated label value in {line:?}"),
+                }
+            };
+            rest = &rest[end + 1..];
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+
+    fn populated_registry() -> MetricsRegistry {
+        let registry = MetricsRegistry::new();
+        let requests = registry
+            .counter(
+                MetricOpts::new("http_requests_total", "Requests \"served\".\nBy method.")
+                    .labels(&["method", "path"]),
+            )
+            .unwrap();
+        requests.with_labels(&["GET", "/a"]).unwrap().inc_by(3);
+        requests
+            .with_labels(&["POST", "/quote\"back\\slash\nnl"])
+            .unwrap()
+            .inc();
+        let inflight = registry
+            .gauge(MetricOpts::new("http_in_flight", "In-flight requests"))
+            .unwrap();
+        inflight.with_labels(&[]).unwrap().set(-2);
+        let latency = registry
+            .histogram(
+                MetricOpts::new("http_request_seconds", "Latency").labels(&["route"]),
+                vec![0.1, 1.0, 10.0],
+            )
+            .unwrap();
+        let series = latency.with_labels(&["/a"]).unwrap();
+        series.observe(0.0625);
+        series.observe_with_exemplar(0.5, "4bf92f3577b34da6a3ce929d0e0e4736");
+        series.observe(50.0);
+        registry
+    }
+
+    #[test]
+    fn prometheus_exposition_conforms_for_every_instrument_type() {
+        let text = populated_registry().render_prometheus();
+        assert_grammar(&text, ExpositionFormat::Prometheus);
+
+        assert!(text.contains("# TYPE http_requests_total counter\n"));
+        assert!(text.contains("# HELP http_requests_total Requests \"served\".\\nBy method.\n"));
+        assert!(text.contains("http_requests_total{method=\"GET\",path=\"/a\"} 3\n"));
+        assert!(text.contains(
+            "http_requests_total{method=\"POST\",path=\"/quote\\\"back\\\\slash\\nnl\"} 1\n"
+        ));
+        assert!(text.contains("# TYPE http_in_flight gauge\nhttp_in_flight -2\n"));
+        assert!(text.contains("http_request_seconds_bucket{route=\"/a\",le=\"0.1\"} 1\n"));
+        assert!(text.contains("http_request_seconds_bucket{route=\"/a\",le=\"1\"} 2\n"));
+        assert!(text.contains("http_request_seconds_bucket{route=\"/a\",le=\"+Inf\"} 3\n"));
+        assert!(text.contains("http_request_seconds_sum{route=\"/a\"} 50.5625\n"));
+        assert!(text.contains("http_request_seconds_count{route=\"/a\"} 3\n"));
+        assert!(
+            !text.contains(" # {"),
+            "Prometheus text carries no exemplars"
+        );
+    }
+
+    #[test]
+    fn openmetrics_exposition_conforms_for_every_instrument_type() {
+        let text = populated_registry().render_openmetrics();
+        assert_grammar(&text, ExpositionFormat::OpenMetrics);
+
+        assert!(text.contains("# TYPE http_requests counter\n"));
+        assert!(text.contains("# HELP http_requests Requests \\\"served\\\".\\nBy method.\n"));
+        assert!(text.contains("http_requests_total{method=\"GET\",path=\"/a\"} 3\n"));
+        assert!(text.contains("http_request_seconds_bucket{route=\"/a\",le=\"1.0\"} 2"));
+        assert!(text.contains("http_request_seconds_bucket{route=\"/a\",le=\"10.0\"} 2\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn exemplar_attached_from_trace_id_in_context() {
+        let registry = MetricsRegistry::new();
+        let latency = registry
+            .histogram(MetricOpts::new("op_seconds", "Op latency"), vec![1.0, 2.0])
+            .unwrap();
+        let series = latency.with_labels(&[]).unwrap();
+
+        series.observe(0.5);
+        let ctx = DiagnosticContext::new().with_custom(TRACE_ID_CONTEXT_FIELD, "abc123");
+        {
+            let _guard = ctx.enter();
+            series.observe(1.5);
+        }
+
+        let snapshot = series.snapshot();
+        assert_eq!(snapshot.exemplars[0], None, "no trace id, no exemplar");
+        assert_eq!(
+            snapshot.exemplars[1],
+            Some(Exemplar {
+                trace_id: "abc123".to_string(),
+                value: 1.5
+            })
+        );
+        let text = registry.render_openmetrics();
+        assert_grammar(&text, ExpositionFormat::OpenMetrics);
+        assert!(text.contains("op_seconds_bucket{le=\"1.0\"} 1\n"));
+        assert!(text.contains("op_seconds_bucket{le=\"2.0\"} 2 # {trace_id=\"abc123\"} 1.5\n"));
+    }
+
+    #[test]
+    fn label_cap_folds_new_label_sets_into_overflow_series() {
+        let registry = MetricsRegistry::with_max_series_per_family(100);
+        let family = registry
+            .counter(
+                MetricOpts::new("jobs_total", "Jobs")
+                    .labels(&["id"])
+                    .max_series(2),
+            )
+            .unwrap();
+        family.with_labels(&["a"]).unwrap().inc();
+        family.with_labels(&["b"]).unwrap().inc();
+        let overflow_c = family.with_labels(&["c"]).unwrap();
+        let overflow_d = family.with_labels(&["d"]).unwrap();
+        overflow_c.inc();
+        overflow_d.inc_by(2);
+        // Existing label sets keep resolving to their own series.
+        family.with_labels(&["a"]).unwrap().inc();
+
+        assert!(Arc::ptr_eq(&overflow_c, &overflow_d));
+        assert_eq!(family.series_count(), 2);
+        assert_eq!(family.overflow_rejections(), 2);
+
+        let text = registry.render_prometheus();
+        assert_grammar(&text, ExpositionFormat::Prometheus);
+        assert!(text.contains("jobs_total{id=\"a\"} 2\n"));
+        assert!(text.contains("jobs_total{id=\"__overflow__\"} 3\n"));
+        assert!(!text.contains("id=\"c\""));
+        assert!(text.contains("asupersync_metrics_series_overflow_total{family=\"jobs\"} 2\n"));
+    }
+
+    #[test]
+    fn callback_families_are_capped_and_rendered_in_label_order() {
+        let registry = MetricsRegistry::new();
+        registry
+            .gauge_fn(
+                MetricOpts::new("queue_depth", "Depth")
+                    .labels(&["queue"])
+                    .max_series(2),
+                || {
+                    vec![
+                        (vec!["c".to_string()], 3),
+                        (vec!["a".to_string()], 1),
+                        (vec!["b".to_string()], 2),
+                        (vec![], 9),
+                    ]
+                },
+            )
+            .unwrap();
+        let text = registry.render_prometheus();
+        assert_grammar(&text, ExpositionFormat::Prometheus);
+        assert!(text.contains("queue_depth{queue=\"a\"} 1\nqueue_depth{queue=\"b\"} 2\n"));
+        assert!(!text.contains("queue=\"c\""));
+        assert!(
+            text.contains("asupersync_metrics_series_overflow_total{family=\"queue_depth\"} 1\n")
+        );
+    }
+
+    #[test]
+    fn registration_validates_names_buckets_and_conflicts() {
+        let registry = MetricsRegistry::new();
+        assert!(matches!(
+            registry.counter(MetricOpts::new("bad-name", "")),
+            Err(RegistryError::InvalidMetricName(_))
+        ));
+        assert!(matches!(
+            registry.gauge(MetricOpts::new("g", "").labels(&["__reserved"])),
+            Err(RegistryError::InvalidLabelName(_))
+        ));
+        assert!(matches!(
+            registry.histogram(MetricOpts::new("h", "").labels(&["le"]), vec![1.0]),
+            Err(RegistryError::InvalidLabelName(_))
+        ));
+        assert!(matches!(
+            registry.histogram(MetricOpts::new("h", ""), vec![2.0, 1.0]),
+            Err(RegistryError::InvalidBuckets(_))
+        ));
+        assert!(matches!(
+            registry.histogram(MetricOpts::new("h", ""), vec![f64::NAN]),
+            Err(RegistryError::InvalidBuckets(_))
+        ));
+
+        let first = registry
+            .histogram(MetricOpts::new("h", ""), vec![1.0, f64::INFINITY])
+            .unwrap();
+        let again = registry
+            .histogram(MetricOpts::new("h", ""), vec![1.0])
+            .unwrap();
+        assert!(
+            Arc::ptr_eq(&first, &again),
+            "identical registration is idempotent"
+        );
+        assert_eq!(
+            registry
+                .histogram(MetricOpts::new("h", ""), vec![2.0])
+                .unwrap_err(),
+            RegistryError::AlreadyRegistered("h".to_string())
+        );
+        assert!(matches!(
+            registry.gauge(MetricOpts::new("h", "")),
+            Err(RegistryError::AlreadyRegistered(_))
+        ));
+        assert_eq!(
+            first.with_labels(&["x"]).unwrap_err(),
+            RegistryError::LabelCardinalityMismatch {
+                family: "h".to_string(),
+                expected: 0,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn concurrent_scrapes_never_observe_torn_histograms() {
+        let registry = Arc::new(MetricsRegistry::new());
+        let family = registry
+            .histogram(
+                MetricOpts::new("work_seconds", "Work").labels(&["worker"]),
+                vec![0.25, 0.5, 0.75],
+            )
+            .unwrap();
+        let stop = Arc::new(AtomicBool::new(false));
+        let writers: Vec<_> = (0..4)
+            .map(|worker| {
+                let series = family.with_labels(&[&worker.to_string()]).unwrap();
+                let stop = Arc::clone(&stop);
+                std::thread::spawn(move || {
+                    let mut i = 0u32;
+                    while !stop.load(Ordering::Relaxed) {
+                        series.observe(f64::from(i % 4) * 0.25);
+                        i = i.wrapping_add(1);
+                    }
+                })
+            })
+            .collect();
+
+        for _ in 0..200 {
+            let text = registry.render_prometheus();
+            let mut inf = BTreeMap::new();
+            let mut count = BTreeMap::new();
+            let mut last_bucket: BTreeMap<String, u64> = BTreeMap::new();
+            for line in text.lines().filter(|line| !line.starts_with('#')) {
+                let (series, value) = line.rsplit_once(' ').unwrap();
+                let value: u64 = value.parse().unwrap_or(0);
+                let worker = series
+                    .split("worker=\"")
+                    .nth(1)
+                    .and_then(|rest| rest.split('"').next())
+                    .unwrap_or_default()
+                    .to_string();
+                if series.starts_with("work_seconds_bucket") {
+                    let previous = last_bucket.insert(worker.clone(), value).unwrap_or(0);
+                    assert!(value >= previous, "buckets must be cumulative: {line}");
+                    if series.contains("le=\"+Inf\"") {
+                        inf.insert(worker, value);
+                    }
+                } else if series.starts_with("work_seconds_count") {
+                    count.insert(worker, value);
+                }
+            }
+            assert_eq!(inf, count, "+Inf bucket must equal _count in one scrape");
+        }
+        stop.store(true, Ordering::Relaxed);
+        for writer in writers {
+            writer.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn accept_header_negotiates_format() {
+        assert_eq!(
+            ExpositionFormat::negotiate(None),
+            ExpositionFormat::Prometheus
+        );
+        assert_eq!(
+            ExpositionFormat::negotiate(Some("text/plain;version=0.0.4")),
+            ExpositionFormat::Prometheus
+        );
+        assert_eq!(
+            ExpositionFormat::negotiate(Some(
+                "application/openmetrics-text;version=1.0.0,text/plain;q=0.5"
+            )),
+            ExpositionFormat::OpenMetrics
+        );
+        assert_eq!(
+            ExpositionFormat::negotiate(Some("application/openmetrics-text;q=0")),
+            ExpositionFormat::Prometheus
+        );
+    }
+
+    #[test]
+    fn handlers_serve_negotiated_exposition() {
+        let registry = Arc::new(populated_registry());
+
+        let handler = MetricsHandler::new(Arc::clone(&registry));
+        let request = crate::web::extract::Request::new("GET", "/metrics")
+            .with_header("Accept", "application/openmetrics-text");
+        let response = futures_lite::future::block_on(crate::web::handler::Handler::call(
+            &handler,
+            &crate::Cx::for_testing(),
+            request,
+        ));
+        assert_eq!(response.status, crate::web::response::StatusCode::OK);
+        assert!(
+            std::str::from_utf8(&response.body)
+                .unwrap()
+                .ends_with("# EOF\n")
+        );
+
+        let mut scrape = HttpRequest::builder(Method::Get, "/metrics").build();
+        scrape
+            .headers
+            .push(("Accept".to_string(), "text/plain".to_string()));
+        let response = scrape_response(&registry, &scrape);
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.header_value("content-type"),
+            Some(ExpositionFormat::Prometheus.content_type())
+        );
+        assert_eq!(
+            scrape_response(
+                &registry,
+                &HttpRequest::builder(Method::Get, "/other").build()
+            )
+            .status,
+            404
+        );
+        assert_eq!(
+            scrape_response(
+                &registry,
+                &HttpRequest::builder(Method::Post, "/metrics").build()
+            )
+            .status,
+            405
+        );
+    }
+
+    #[test]
+    fn runtime_snapshot_counts_render_as_gauges() {
+        let registry = MetricsRegistry::new();
+        registry
+            .register_runtime_snapshot(|| crate::runtime::RuntimeState::new().snapshot())
+            .unwrap();
+        let text = registry.render_prometheus();
+        assert_grammar(&text, ExpositionFormat::Prometheus);
+        assert!(text.contains("# TYPE asupersync_runtime_entities gauge\n"));
+        assert!(text.contains("asupersync_runtime_entities{kind=\"task\"} 0\n"));
+        assert!(text.contains("asupersync_runtime_entities{kind=\"region\"}"));
+    }
+}
@@ -1,12 +1,16 @@
 //! Logging severity levels.
 
 use core::fmt;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 /// Severity level for log entries.
 ///
 /// Levels are ordered: Trace < Debug < Info < Warn < Error.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     /// Detailed tracing information (lowest priority).
     Trace,
@@ -0,0 +1,1361 @@
+//! Runtime control of log verbosity.
+//!
+//! Every [`LogCollector`](super::LogCollector) owns a [`LogControl`] that
+//! changes what it records without a restart: the minimum level and a
+//! sampling rate can be overridden globally, for one region (by telemetry
+//! name or id), or for every event whose message starts with a prefix.
+//!
+//! # Precedence
+//!
+//! For each entry the most specific override wins: the longest matching
+//! event prefix, then the entry's region, then the global override, then the
+//! collector's base level. Level and sampling rate resolve independently, and
+//! an entry is sampled only after it passes the level check. Sampling is
+//! counter-based, so a rate of `0.25` keeps exactly every fourth entry.
+//!
+//! # Lifetime
+//!
+//! An override set with a TTL stacks on top of whatever was in force for its
+//! scope and pops off once the clock passes its deadline, restoring the
+//! previous value. Expiry is checked against the emitting entry's timestamp,
+//! so it follows virtual time under the lab runtime. A permanent change
+//! replaces the whole stack; a permanent global level becomes the collector's
+//! base level.
+//!
+//! # Cost
+//!
+//! A batch of changes is applied under a writer lock and published as one
+//! immutable configuration with a new epoch. The emit path caches that
+//! configuration per thread and only re-reads it when the epoch moves, so it
+//! never takes a lock. With no override in force it is skipped entirely and
+//! the collector checks its base level exactly as before.
+//!
+//! # Audit
+//!
+//! Each change, including an expiry, produces a [`LogControlAudit`] record
+//! naming the actor, the scope, and the previous and new values. Records are
+//! retained by the control (the newest [`AUDIT_CAPACITY`]) and written to the
+//! collector under [`LOG_CONTROL_TARGET`] regardless of its level.
+//!
+//! # Console protocol
+//!
+//! [`LogControlRequest`] and [`LogControlResponse`] are the JSON wire format,
+//! versioned by [`LOG_CONTROL_WIRE_SCHEMA_V1`]; [`LogControl::handle_request`]
+//! serves them.
+
+use super::collector::{CollectorInner, level_from_rank};
+use super::entry::LogEntry;
+use super::level::LogLevel;
+use super::tenant::{REGION_ID_FIELD, REGION_NAME_FIELD};
+use crate::types::Time;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Stable schema identifier for log-control console messages.
+pub const LOG_CONTROL_WIRE_SCHEMA_V1: &str = "asupersync.log_control_wire.v1";
+
+/// Target of the audit entries a [`LogControl`] writes to its collector.
+pub const LOG_CONTROL_TARGET: &str = "asupersync::observability::log_control";
+
+/// Number of audit records a [`LogControl`] retains.
+pub const AUDIT_CAPACITY: usize = 1024;
+
+/// Actor recorded for changes made by TTL expiry.
+pub const EXPIRY_ACTOR: &str = "ttl-expiry";
+
+const NANOS_PER_MILLI: u64 = 1_000_000;
+
+static NEXT_CONTROL_ID: AtomicU64 = AtomicU64::new(1);
+
+/// What an override applies to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum LogScope {
+    /// Every entry.
+    Global,
+    /// Entries attributed to a region, named by its telemetry name or by its
+    /// id as it appears in the `region_id` field.
+    Region(String),
+    /// Entries whose message starts with this prefix.
+    Event(String),
+}
+
+impl fmt::Display for LogScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Region(region) => write!(f, "region:{region}"),
+            Self::Event(prefix) => write!(f, "event:{prefix}"),
+        }
+    }
+}
+
+/// The two settings an override can carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSettingKind {
+    /// Minimum level.
+    Level,
+    /// Fraction of entries kept.
+    SampleRate,
+}
+
+/// A value for one [`LogSettingKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSetting {
+    /// Entries below this level are dropped.
+    Level(LogLevel),
+    /// Keep this fraction of entries, in `0.0..=1.0`.
+    SampleRate(f64),
+}
+
+impl LogSetting {
+    /// Returns which setting this value is for.
+    #[must_use]
+    pub const fn kind(self) -> LogSettingKind {
+        match self {
+            Self::Level(_) => LogSettingKind::Level,
+            Self::SampleRate(_) => LogSettingKind::SampleRate,
+        }
+    }
+}
+
+impl fmt::Display for LogSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Level(level) => f.write_str(level.as_str_lower()),
+            Self::SampleRate(rate) => write!(f, "{rate}"),
+        }
+    }
+}
+
+/// One change in a batch passed to [`LogControl::apply`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "op")]
+pub enum LogChange {
+    /// Overrides a setting for a scope.
+    Set {
+        /// Scope of the override.
+        scope: LogScope,
+        /// New value.
+        setting: LogSetting,
+        /// Milliseconds until the override expires; `None` keeps it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ttl_ms: Option<u64>,
+    },
+    /// Removes every override of one setting for a scope.
+    Clear {
+        /// Scope to clear.
+        scope: LogScope,
+        /// Setting to clear.
+        kind: LogSettingKind,
+    },
+}
+
+impl LogChange {
+    /// Sets the minimum level for `scope`.
+    #[must_use]
+    pub const fn level(scope: LogScope, level: LogLevel) -> Self {
+        Self::Set {
+            scope,
+            setting: LogSetting::Level(level),
+            ttl_ms: None,
+        }
+    }
+
+    /// Sets the sampling rate for `scope`.
+    #[must_use]
+    pub const fn sample_rate(scope: LogScope, rate: f64) -> Self {
+        Self::Set {
+            scope,
+            setting: LogSetting::SampleRate(rate),
+            ttl_ms: None,
+        }
+    }
+
+    /// Clears the `kind` overrides for `scope`.
+    #[must_use]
+    pub const fn clear(scope: LogScope, kind: LogSettingKind) -> Self {
+        Self::Clear { scope, kind }
+    }
+
+    /// Makes a [`LogChange::Set`] expire `ttl` after it is applied.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        if let Self::Set { ttl_ms, .. } = &mut self {
+            *ttl_ms = Some(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX));
+        }
+        self
+    }
+
+    /// Returns the scope the change applies to.
+    #[must_use]
+    pub const fn scope(&self) -> &LogScope {
+        match self {
+            Self::Set { scope, .. } | Self::Clear { scope, .. } => scope,
+        }
+    }
+
+    fn key(&self) -> Key {
+        match self {
+            Self::Set { scope, setting, .. } => (scope.clone(), setting.kind()),
+            Self::Clear { scope, kind } => (scope.clone(), *kind),
+        }
+    }
+
+    fn validate(&self) -> Result<(), LogControlError> {
+        let scope = self.scope();
+        if let LogScope::Region(name) | LogScope::Event(name) = scope
+            && name.is_empty()
+        {
+            return Err(LogControlError::EmptyScope(scope.clone()));
+        }
+        match self {
+            Self::Set {
+                setting: LogSetting::SampleRate(rate),
+                ..
+            } if !(0.0..=1.0).contains(rate) => Err(LogControlError::InvalidSampleRate {
+                scope: scope.clone(),
+                rate: *rate,
+            }),
+            Self::Set {
+                ttl_ms: Some(0),
+                ..
+            } => Err(LogControlError::ZeroTtl(scope.clone())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error returned when a batch of changes is rejected; none of it applies.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogControlError {
+    /// Region names and event prefixes must be non-empty.
+    EmptyScope(LogScope),
+    /// Sampling rates must lie in `0.0..=1.0`.
+    InvalidSampleRate {
+        /// Scope of the rejected change.
+        scope: LogScope,
+        /// The rejected rate.
+        rate: f64,
+    },
+    /// A TTL must be at least one millisecond.
+    ZeroTtl(LogScope),
+}
+
+impl fmt::Display for LogControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyScope(scope) => write!(f, "log control scope `{scope}` has an empty name"),
+            Self::InvalidSampleRate { scope, rate } => {
+                write!(f, "sample rate {rate} for `{scope}` is outside 0.0..=1.0")
+            }
+            Self::ZeroTtl(scope) => write!(f, "log control override for `{scope}` has a zero TTL"),
+        }
+    }
+}
+
+impl std::error::Error for LogControlError {}
+
+/// Why an audit record was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogControlCause {
+    /// A [`LogChange::Set`].
+    Set,
+    /// A [`LogChange::Clear`].
+    Clear,
+    /// One or more TTL overrides expired.
+    Expired,
+}
+
+/// Record of one change to a [`LogControl`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogControlAudit {
+    /// Epoch the change was published in.
+    pub epoch: u64,
+    /// When the change was applied.
+    pub at: Time,
+    /// Who made the change, or [`EXPIRY_ACTOR`].
+    pub actor: String,
+    /// What kind of change it was.
+    pub cause: LogControlCause,
+    /// Scope of the change.
+    pub scope: LogScope,
+    /// Setting that changed.
+    pub kind: LogSettingKind,
+    /// Value in force before the change, if any.
+    pub previous: Option<LogSetting>,
+    /// Value in force after the change, if any.
+    pub current: Option<LogSetting>,
+    /// Deadline of the new override, or of the override that expired.
+    pub expires_at: Option<Time>,
+}
+
+impl LogControlAudit {
+    fn to_entry(&self) -> LogEntry {
+        let show =
+            |setting: Option<LogSetting>| setting.map_or_else(|| "-".into(), |s| s.to_string());
+        let entry = LogEntry::info("log control change")
+            .with_target(LOG_CONTROL_TARGET)
+            .with_timestamp(self.at)
+            .with_field("epoch", self.epoch.to_string())
+            .with_field("actor", self.actor.as_str())
+            .with_field("cause", format!("{:?}", self.cause).to_lowercase())
+            .with_field("scope", self.scope.to_string())
+            .with_field("previous", show(self.previous))
+            .with_field("current", show(self.current));
+        match self.expires_at {
+            Some(at) => entry.with_field("expires_at_ms", at.as_millis().to_string()),
+            None => entry,
+        }
+    }
+}
+
+/// An override in force, as reported by [`LogControl::effective`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogOverride {
+    /// Scope of the override.
+    pub scope: LogScope,
+    /// Value in force.
+    pub setting: LogSetting,
+    /// Who set it.
+    pub actor: String,
+    /// When it was set.
+    pub set_at: Time,
+    /// When it expires, if it has a TTL.
+    pub expires_at: Option<Time>,
+}
+
+/// The effective configuration of a [`LogControl`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogControlSnapshot {
+    /// Epoch of the published configuration.
+    pub epoch: u64,
+    /// The collector's base level.
+    pub base_level: LogLevel,
+    /// The override in force for each scope and setting, in scope order.
+    pub overrides: Vec<LogOverride>,
+}
+
+impl LogControlSnapshot {
+    /// Returns the value in force for `scope` and `kind`, if overridden.
+    #[must_use]
+    pub fn setting(&self, scope: &LogScope, kind: LogSettingKind) -> Option<LogSetting> {
+        self.overrides
+            .iter()
+            .find(|o| &o.scope == scope && o.setting.kind() == kind)
+            .map(|o| o.setting)
+    }
+}
+
+/// A console request to a [`LogControl`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "command")]
+pub enum LogControlCommand {
+    /// Apply a batch of changes atomically.
+    Apply {
+        /// Who is making the change, for the audit trail.
+        actor: String,
+        /// Changes to apply.
+        changes: Vec<LogChange>,
+    },
+    /// Report the effective configuration.
+    Query,
+    /// Report the retained audit records.
+    Audit,
+}
+
+/// Wire envelope for a [`LogControlCommand`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogControlRequest {
+    /// Schema version identifier.
+    pub schema_version: String,
+    /// The command.
+    pub command: LogControlCommand,
+}
+
+impl LogControlRequest {
+    /// Wraps `command` in the current schema version.
+    #[must_use]
+    pub fn new(command: LogControlCommand) -> Self {
+        Self {
+            schema_version: LOG_CONTROL_WIRE_SCHEMA_V1.to_string(),
+            command,
+        }
+    }
+
+    /// Returns true when the payload schema matches the expected version.
+    #[must_use]
+    pub fn has_expected_schema(&self) -> bool {
+        self.schema_version == LOG_CONTROL_WIRE_SCHEMA_V1
+    }
+
+    /// Encode request as compact JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when serialization fails.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Decode request from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when parsing fails.
+    pub fn from_json(payload: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+/// Result of a [`LogControlCommand`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reply")]
+pub enum LogControlReply {
+    /// The batch was applied and published in `epoch`.
+    Applied {
+        /// Epoch of the new configuration.
+        epoch: u64,
+    },
+    /// The effective configuration.
+    Effective {
+        /// The configuration.
+        snapshot: LogControlSnapshot,
+    },
+    /// The retained audit records, oldest first.
+    Audit {
+        /// The records.
+        records: Vec<LogControlAudit>,
+    },
+    /// The request was rejected and nothing changed.
+    Rejected {
+        /// Why it was rejected.
+        reason: String,
+    },
+}
+
+/// Wire envelope for a [`LogControlReply`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogControlResponse {
+    /// Schema version identifier.
+    pub schema_version: String,
+    /// Logical time the request was served at.
+    pub at: Time,
+    /// The reply.
+    pub reply: LogControlReply,
+}
+
+impl LogControlResponse {
+    /// Wraps `reply` in the current schema version.
+    #[must_use]
+    pub fn new(at: Time, reply: LogControlReply) -> Self {
+        Self {
+            schema_version: LOG_CONTROL_WIRE_SCHEMA_V1.to_string(),
+            at,
+            reply,
+        }
+    }
+
+    /// Returns true when the payload schema matches the expected version.
+    #[must_use]
+    pub fn has_expected_schema(&self) -> bool {
+        self.schema_version == LOG_CONTROL_WIRE_SCHEMA_V1
+    }
+
+    /// Encode response as compact JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when serialization fails.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Decode response from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when parsing fails.
+    pub fn from_json(payload: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+type Key = (LogScope, LogSettingKind);
+
+const GLOBAL_LEVEL: Key = (LogScope::Global, LogSettingKind::Level);
+
+/// Keeps `rate` of the entries it sees, spread evenly in arrival order.
+#[derive(Debug)]
+struct Sampler {
+    rate: f64,
+    seen: AtomicU64,
+}
+
+impl Sampler {
+    /// The n-th entry passes when it moves `floor(n * rate)` up by one.
+    #[allow(clippy::cast_precision_loss)]
+    fn admit(&self) -> bool {
+        let n = self.seen.fetch_add(1, Ordering::Relaxed);
+        ((n + 1) as f64 * self.rate).floor() > (n as f64 * self.rate).floor()
+    }
+}
+
+#[derive(Debug)]
+struct Layer {
+    setting: LogSetting,
+    actor: String,
+    set_at: Time,
+    expires_at: Option<Time>,
+    /// Created with the layer so its count survives later republishes.
+    sampler: Option<Arc<Sampler>>,
+}
+
+impl Layer {
+    fn expired(&self, now: Time) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Overrides for one setting, resolved by precedence.
+#[derive(Debug)]
+struct Rules<T> {
+    global: Option<T>,
+    regions: BTreeMap<String, T>,
+    /// Longest prefix first.
+    events: Vec<(String, T)>,
+}
+
+impl<T> Default for Rules<T> {
+    fn default() -> Self {
+        Self {
+            global: None,
+            regions: BTreeMap::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<T> Rules<T> {
+    fn insert(&mut self, scope: &LogScope, value: T) {
+        match scope {
+            LogScope::Global => self.global = Some(value),
+            LogScope::Region(region) => {
+                self.regions.insert(region.clone(), value);
+            }
+            LogScope::Event(prefix) => self.events.push((prefix.clone(), value)),
+        }
+    }
+
+    fn resolve(&self, message: &str, region_id: Option<&str>, region: Option<&str>) -> Option<&T> {
+        self.events
+            .iter()
+            .find(|(prefix, _)| message.starts_with(prefix.as_str()))
+            .map(|(_, value)| value)
+            .or_else(|| region_id.and_then(|id| self.regions.get(id)))
+            .or_else(|| region.and_then(|name| self.regions.get(name)))
+            .or(self.global.as_ref())
+    }
+}
+
+/// The immutable configuration the emit path reads.
+#[derive(Debug, Default)]
+struct Effective {
+    levels: Rules<LogLevel>,
+    samples: Rules<Arc<Sampler>>,
+    next_expiry: Option<Time>,
+}
+
+impl Effective {
+    fn admit(&self, entry: &LogEntry, region: Option<&str>, base: LogLevel) -> bool {
+        let message = entry.message();
+        let region_id = entry.get_field(REGION_ID_FIELD);
+        let region = region.or_else(|| entry.get_field(REGION_NAME_FIELD));
+        let threshold = self
+            .levels
+            .resolve(message, region_id, region)
+            .copied()
+            .unwrap_or(base);
+        entry.level().is_enabled_at(threshold)
+            && self
+                .samples
+                .resolve(message, region_id, region)
+                .is_none_or(|sampler| sampler.admit())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Writer {
+    layers: BTreeMap<Key, Vec<Layer>>,
+    audit: VecDeque<LogControlAudit>,
+}
+
+impl Writer {
+    fn current(&self, key: &Key, base: LogLevel) -> Option<LogSetting> {
+        self.layers
+            .get(key)
+            .and_then(|stack| stack.last())
+            .map(|layer| layer.setting)
+            .or_else(|| (*key == GLOBAL_LEVEL).then_some(LogSetting::Level(base)))
+    }
+
+    fn change(
+        &mut self,
+        change: &LogChange,
+        base: &mut LogLevel,
+        actor: &str,
+        at: Time,
+        epoch: u64,
+    ) -> LogControlAudit {
+        let key = change.key();
+        let previous = self.current(&key, *base);
+        let (cause, expires_at) = match change {
+            LogChange::Set {
+                setting,
+                ttl_ms,
+                ..
+            } => {
+                let expires_at =
+                    ttl_ms.map(|ttl| at.saturating_add_nanos(ttl.saturating_mul(NANOS_PER_MILLI)));
+                let stack = self.layers.entry(key.clone()).or_default();
+                if expires_at.is_none() {
+                    stack.clear();
+                }
+                match setting {
+                    LogSetting::Level(level) if expires_at.is_none() && key == GLOBAL_LEVEL => {
+                        *base = *level;
+                    }
+                    _ => stack.push(Layer {
+                        setting: *setting,
+                        actor: actor.to_string(),
+                        set_at: at,
+                        expires_at,
+                        sampler: match setting {
+                            LogSetting::SampleRate(rate) => Some(Arc::new(Sampler {
+                                rate: *rate,
+                                seen: AtomicU64::new(0),
+                            })),
+                            LogSetting::Level(_) => None,
+                        },
+                    }),
+                }
+                if stack.is_empty() {
+                    self.layers.remove(&key);
+                }
+                (LogControlCause::Set, expires_at)
+            }
+            LogChange::Clear { .. } => {
+                self.layers.remove(&key);
+                (LogControlCause::Clear, None)
+            }
+        };
+        let current = self.current(&key, *base);
+        self.record(LogControlAudit {
+            epoch,
+            at,
+            actor: actor.to_string(),
+            cause,
+            scope: key.0,
+            kind: key.1,
+            previous,
+            current,
+            expires_at,
+        })
+    }
+
+    fn has_expired(&self, now: Time) -> bool {
+        self.layers.values().flatten().any(|layer| layer.expired(now))
+    }
+
+    /// Drops every expired layer, with one record per affected stack.
+    fn expire(&mut self, base: LogLevel, now: Time, epoch: u64) -> Vec<LogControlAudit> {
+        let keys: Vec<Key> = self
+            .layers
+            .iter()
+            .filter(|(_, stack)| stack.iter().any(|layer| layer.expired(now)))
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut records = Vec::with_capacity(keys.len());
+        for key in keys {
+            let previous = self.current(&key, base);
+            let Some(stack) = self.layers.get_mut(&key) else {
+                continue;
+            };
+            let expires_at = stack
+                .iter()
+                .filter(|layer| layer.expired(now))
+                .filter_map(|layer| layer.expires_at)
+                .max();
+            stack.retain(|layer| !layer.expired(now));
+            if stack.is_empty() {
+                self.layers.remove(&key);
+            }
+            let current = self.current(&key, base);
+            records.push(self.record(LogControlAudit {
+                epoch,
+                at: now,
+                actor: EXPIRY_ACTOR.to_string(),
+                cause: LogControlCause::Expired,
+                scope: key.0,
+                kind: key.1,
+                previous,
+                current,
+                expires_at,
+            }));
+        }
+        records
+    }
+
+    fn record(&mut self, record: LogControlAudit) -> LogControlAudit {
+        if self.audit.len() >= AUDIT_CAPACITY {
+            self.audit.pop_front();
+        }
+        self.audit.push_back(record.clone());
+        record
+    }
+
+    fn effective(&self) -> Effective {
+        let mut effective = Effective::default();
+        for ((scope, _), stack) in &self.layers {
+            let Some(top) = stack.last() else {
+                continue;
+            };
+            match (top.setting, &top.sampler) {
+                (LogSetting::Level(level), _) => effective.levels.insert(scope, level),
+                (LogSetting::SampleRate(_), Some(sampler)) => {
+                    effective.samples.insert(scope, Arc::clone(sampler));
+                }
+                (LogSetting::SampleRate(_), None) => {}
+            }
+        }
+        effective.levels.events.sort_by_key(|(prefix, _)| Reverse(prefix.len()));
+        effective.samples.events.sort_by_key(|(prefix, _)| Reverse(prefix.len()));
+        effective.next_expiry = self
+            .layers
+            .values()
+            .flatten()
+            .filter_map(|layer| layer.expires_at)
+            .min();
+        effective
+    }
+
+    fn snapshot(&self, epoch: u64, base_level: LogLevel) -> LogControlSnapshot {
+        let overrides = self
+            .layers
+            .iter()
+            .filter_map(|((scope, _), stack)| {
+                stack.last().map(|top| LogOverride {
+                    scope: scope.clone(),
+                    setting: top.setting,
+                    actor: top.actor.clone(),
+                    set_at: top.set_at,
+                    expires_at: top.expires_at,
+                })
+            })
+            .collect();
+        LogControlSnapshot {
+            epoch,
+            base_level,
+            overrides,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    id: u64,
+    base: Arc<AtomicU8>,
+    sink: Arc<Mutex<CollectorInner>>,
+    active: AtomicBool,
+    epoch: AtomicU64,
+    config: RwLock<Arc<Effective>>,
+    writer: Mutex<Writer>,
+    evaluations: AtomicU64,
+}
+
+struct Cached {
+    control: u64,
+    epoch: u64,
+    effective: Arc<Effective>,
+}
+
+thread_local! {
+    static CACHED: RefCell<Option<Cached>> = const { RefCell::new(None) };
+}
+
+/// Runtime-adjustable level and sampling overrides for one collector.
+///
+/// Obtained from [`LogCollector::control`](super::LogCollector::control) or
+/// [`RuntimeHandle::log_control`](crate::runtime::RuntimeHandle::log_control);
+/// clones share state.
+#[derive(Clone)]
+pub struct LogControl {
+    shared: Arc<Shared>,
+}
+
+impl LogControl {
+    pub(super) fn new(base: Arc<AtomicU8>, sink: Arc<Mutex<CollectorInner>>) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                id: NEXT_CONTROL_ID.fetch_add(1, Ordering::Relaxed),
+                base,
+                sink,
+                active: AtomicBool::new(false),
+                epoch: AtomicU64::new(0),
+                config: RwLock::new(Arc::new(Effective::default())),
+                writer: Mutex::new(Writer::default()),
+                evaluations: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Applies `changes` as one batch on behalf of `actor` and returns the
+    /// epoch it was published in.
+    ///
+    /// Either every change applies or, if any is invalid, none does.
+    pub fn apply(
+        &self,
+        actor: &str,
+        changes: &[LogChange],
+        now: Time,
+    ) -> Result<u64, LogControlError> {
+        for change in changes {
+            change.validate()?;
+        }
+        let mut writer = self.shared.writer.lock();
+        let mut base = self.base_level();
+        let epoch = self.next_epoch();
+        let records = changes
+            .iter()
+            .map(|change| writer.change(change, &mut base, actor, now, epoch))
+            .collect();
+        Ok(self.publish(&writer, base, records))
+    }
+
+    /// Drops every override whose TTL has passed by `now`, restoring what
+    /// was in force beneath it. Returns how many scopes changed.
+    ///
+    /// The collector calls this as entries arrive; call it directly to expire
+    /// overrides while nothing is being logged.
+    pub fn expire(&self, now: Time) -> usize {
+        let mut writer = self.shared.writer.lock();
+        if !writer.has_expired(now) {
+            return 0;
+        }
+        let base = self.base_level();
+        let epoch = self.next_epoch();
+        let records = writer.expire(base, now, epoch);
+        let expired = records.len();
+        self.publish(&writer, base, records);
+        expired
+    }
+
+    /// Returns the configuration in force at `now`, after expiring overrides.
+    #[must_use]
+    pub fn effective(&self, now: Time) -> LogControlSnapshot {
+        self.expire(now);
+        let writer = self.shared.writer.lock();
+        writer.snapshot(self.epoch(), self.base_level())
+    }
+
+    /// Returns the retained audit records, oldest first.
+    #[must_use]
+    pub fn audit(&self) -> Vec<LogControlAudit> {
+        self.shared.writer.lock().audit.iter().cloned().collect()
+    }
+
+    /// Serves one console request.
+    #[must_use]
+    pub fn handle_request(&self, request: &LogControlRequest, now: Time) -> LogControlResponse {
+        let reply = if request.has_expected_schema() {
+            match &request.command {
+                LogControlCommand::Apply { actor, changes } => {
+                    match self.apply(actor, changes, now) {
+                        Ok(epoch) => LogControlReply::Applied { epoch },
+                        Err(err) => LogControlReply::Rejected {
+                            reason: err.to_string(),
+                        },
+                    }
+                }
+                LogControlCommand::Query => LogControlReply::Effective {
+                    snapshot: self.effective(now),
+                },
+                LogControlCommand::Audit => LogControlReply::Audit {
+                    records: self.audit(),
+                },
+            }
+        } else {
+            LogControlReply::Rejected {
+                reason: format!("unsupported schema version `{}`", request.schema_version),
+            }
+        };
+        LogControlResponse::new(now, reply)
+    }
+
+    /// Returns the epoch of the published configuration.
+    #[must_use]
+    pub fn epoch(&self) -> u64 {
+        self.shared.epoch.load(Ordering::Acquire)
+    }
+
+    /// Returns true while any override is in force.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.shared.active.load(Ordering::Acquire)
+    }
+
+    /// Returns how many entries have been checked against overrides.
+    #[must_use]
+    pub fn evaluations(&self) -> u64 {
+        self.shared.evaluations.load(Ordering::Relaxed)
+    }
+
+    /// Decides whether `entry` is recorded; only called while
+    /// [`is_active`](Self::is_active).
+    pub(super) fn admit(&self, entry: &LogEntry, region: Option<&str>, now: Time) -> bool {
+        self.shared.evaluations.fetch_add(1, Ordering::Relaxed);
+        if self.with_effective(|effective| effective.next_expiry.is_some_and(|at| at <= now)) {
+            self.expire(now);
+        }
+        let base = self.base_level();
+        self.with_effective(|effective| effective.admit(entry, region, base))
+    }
+
+    fn base_level(&self) -> LogLevel {
+        level_from_rank(self.shared.base.load(Ordering::Relaxed))
+    }
+
+    fn next_epoch(&self) -> u64 {
+        self.shared.epoch.load(Ordering::Acquire) + 1
+    }
+
+    /// Swaps in the configuration built from `writer`; callers hold the
+    /// writer lock, so epochs are published in order.
+    fn publish(&self, writer: &Writer, base: LogLevel, records: Vec<LogControlAudit>) -> u64 {
+        *self.shared.config.write() = Arc::new(writer.effective());
+        self.shared.base.store(base as u8, Ordering::Relaxed);
+        self.shared
+            .active
+            .store(!writer.layers.is_empty(), Ordering::Release);
+        let epoch = self.shared.epoch.fetch_add(1, Ordering::AcqRel) + 1;
+        let mut sink = self.shared.sink.lock();
+        for record in records {
+            sink.push(record.to_entry());
+        }
+        epoch
+    }
+
+    /// Runs `f` on the published configuration, re-reading it only when the
+    /// epoch has moved since this thread last looked.
+    fn with_effective<R>(&self, f: impl FnOnce(&Effective) -> R) -> R {
+        let epoch = self.shared.epoch.load(Ordering::Acquire);
+        let cached = CACHED.with(|slot| slot.borrow_mut().take());
+        let cached = match cached {
+            Some(cached) if cached.control == self.shared.id && cached.epoch == epoch => cached,
+            _ => Cached {
+                control: self.shared.id,
+                epoch,
+                effective: Arc::clone(&self.shared.config.read()),
+            },
+        };
+        let result = f(&cached.effective);
+        CACHED.with(|slot| *slot.borrow_mut() = Some(cached));
+        result
+    }
+}
+
+impl fmt::Debug for LogControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogControl")
+            .field("epoch", &self.epoch())
+            .field("active", &self.is_active())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::observability::LogCollector;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn at(millis: u64) -> Time {
+        Time::from_millis(millis)
+    }
+
+    fn log_in(collector: &LogCollector, entry: LogEntry, region: &str, millis: u64) {
+        let entry = entry.with_field(REGION_NAME_FIELD, region);
+        collector.log(entry.with_timestamp(at(millis)));
+    }
+
+    fn recorded(collector: &LogCollector) -> Vec<String> {
+        collector
+            .drain()
+            .into_iter()
+            .filter(|entry| entry.target() != Some(LOG_CONTROL_TARGET))
+            .map(|entry| entry.message().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn event_prefix_beats_region_beats_global() {
+        init_test("event_prefix_beats_region_beats_global");
+        let collector = LogCollector::new(64);
+        let control = collector.control().clone();
+        control
+            .apply(
+                "oncall",
+                &[
+                    LogChange::level(LogScope::Global, LogLevel::Warn)
+                        .with_ttl(Duration::from_secs(60)),
+                    LogChange::level(LogScope::Region("orders".into()), LogLevel::Debug),
+                    LogChange::level(LogScope::Event("db.".into()), LogLevel::Error),
+                    LogChange::level(LogScope::Event("db.slow".into()), LogLevel::Trace),
+                ],
+                at(0),
+            )
+            .expect("valid batch");
+
+        log_in(&collector, LogEntry::debug("http request"), "orders", 1);
+        log_in(&collector, LogEntry::warn("db.query"), "orders", 1);
+        log_in(&collector, LogEntry::trace("db.slow query"), "orders", 1);
+        log_in(&collector, LogEntry::info("http request"), "billing", 1);
+        collector.log(LogEntry::warn("unscoped").with_timestamp(at(1)));
+
+        let messages = recorded(&collector);
+        crate::assert_with_log!(
+            messages == ["http request", "db.slow query", "unscoped"],
+            "most specific override wins",
+            "[http request, db.slow query, unscoped]",
+            messages
+        );
+        crate::test_complete!("event_prefix_beats_region_beats_global");
+    }
+
+    #[test]
+    fn ttl_expiry_restores_previous_value() {
+        init_test("ttl_expiry_restores_previous_value");
+        let collector = LogCollector::new(64);
+        let control = collector.control().clone();
+        let region = LogScope::Region("orders".into());
+        control
+            .apply(
+                "oncall",
+                &[LogChange::level(region.clone(), LogLevel::Warn)],
+                at(0),
+            )
+            .expect("permanent");
+        control
+            .apply(
+                "oncall",
+                &[LogChange::level(region.clone(), LogLevel::Debug)
+                    .with_ttl(Duration::from_millis(500))],
+                at(100),
+            )
+            .expect("ttl");
+
+        log_in(&collector, LogEntry::debug("inside ttl"), "orders", 599);
+        log_in(&collector, LogEntry::info("after ttl"), "orders", 600);
+        log_in(&collector, LogEntry::warn("still warn"), "orders", 601);
+        let messages = recorded(&collector);
+        crate::assert_with_log!(
+            messages == ["inside ttl", "still warn"],
+            "debug override lapses at its deadline",
+            "[inside ttl, still warn]",
+            messages
+        );
+
+        let snapshot = control.effective(at(700));
+        let level = snapshot.setting(&region, LogSettingKind::Level);
+        crate::assert_with_log!(
+            level == Some(LogSetting::Level(LogLevel::Warn)),
+            "previous override restored",
+            Some(LogSetting::Level(LogLevel::Warn)),
+            level
+        );
+
+        // A global TTL lapses back to the base level and the fast path.
+        control
+            .apply(
+                "oncall",
+                &[
+                    LogChange::clear(region, LogSettingKind::Level),
+                    LogChange::level(LogScope::Global, LogLevel::Trace)
+                        .with_ttl(Duration::from_millis(10)),
+                ],
+                at(700),
+            )
+            .expect("global ttl");
+        let expired = control.expire(at(710));
+        crate::assert_with_log!(expired == 1, "one scope expired", 1, expired);
+        let active = control.is_active();
+        crate::assert_with_log!(!active, "fast path again", false, active);
+        crate::assert_with_log!(
+            collector.min_level() == LogLevel::Info,
+            "base level untouched",
+            LogLevel::Info,
+            collector.min_level()
+        );
+        crate::test_complete!("ttl_expiry_restores_previous_value");
+    }
+
+    #[test]
+    fn no_overrides_skip_evaluation() {
+        init_test("no_overrides_skip_evaluation");
+        let collector = LogCollector::new(16);
+        let control = collector.control().clone();
+        control
+            .apply(
+                "ops",
+                &[LogChange::level(LogScope::Global, LogLevel::Debug)],
+                at(0),
+            )
+            .expect("global level");
+        for _ in 0..8 {
+            collector.log(LogEntry::debug("hot"));
+        }
+        crate::assert_with_log!(
+            control.evaluations() == 0,
+            "permanent global level stays on the base fast path",
+            0,
+            control.evaluations()
+        );
+        crate::assert_with_log!(
+            collector.min_level() == LogLevel::Debug,
+            "base level moved",
+            LogLevel::Debug,
+            collector.min_level()
+        );
+        let count = recorded(&collector).len();
+        crate::assert_with_log!(count == 8, "debug entries recorded", 8, count);
+        crate::test_complete!("no_overrides_skip_evaluation");
+    }
+
+    #[test]
+    fn sample_rate_keeps_an_even_fraction() {
+        init_test("sample_rate_keeps_an_even_fraction");
+        let collector = LogCollector::new(64);
+        let control = collector.control().clone();
+        control
+            .apply(
+                "ops",
+                &[LogChange::sample_rate(LogScope::Event("tick".into()), 0.25)],
+                at(0),
+            )
+            .expect("sample rate");
+        for i in 0..12 {
+            collector.log(LogEntry::info(format!("tick {i}")));
+        }
+        collector.log(LogEntry::info("other"));
+        let messages = recorded(&collector);
+        crate::assert_with_log!(
+            messages == ["tick 3", "tick 7", "tick 11", "other"],
+            "every fourth tick kept",
+            "[tick 3, tick 7, tick 11, other]",
+            messages
+        );
+        let err = control
+            .apply(
+                "ops",
+                &[
+                    LogChange::level(LogScope::Global, LogLevel::Error),
+                    LogChange::sample_rate(LogScope::Global, 1.5),
+                ],
+                at(1),
+            )
+            .expect_err("rate out of range");
+        crate::assert_with_log!(
+            matches!(err, LogControlError::InvalidSampleRate { .. }),
+            "batch rejected",
+            "InvalidSampleRate",
+            err
+        );
+        crate::assert_with_log!(
+            collector.min_level() == LogLevel::Info,
+            "rejected batch changed nothing",
+            LogLevel::Info,
+            collector.min_level()
+        );
+        crate::test_complete!("sample_rate_keeps_an_even_fraction");
+    }
+
+    #[test]
+    fn batches_publish_atomically() {
+        init_test("batches_publish_atomically");
+        let collector = LogCollector::new(0);
+        let control = collector.control().clone();
+        let pair = |level| {
+            [
+                LogChange::level(LogScope::Region("a".into()), level),
+                LogChange::level(LogScope::Region("b".into()), level),
+            ]
+        };
+        control
+            .apply("init", &pair(LogLevel::Info), at(0))
+            .expect("init");
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let writers: Vec<_> = [LogLevel::Debug, LogLevel::Error]
+            .into_iter()
+            .map(|level| {
+                let control = control.clone();
+                let stop = Arc::clone(&stop);
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        control.apply("writer", &pair(level), at(1)).expect("apply");
+                    }
+                })
+            })
+            .collect();
+        let mut torn = 0;
+        for _ in 0..20_000 {
+            let (a, b) = control.with_effective(|effective| {
+                (
+                    effective.levels.regions.get("a").copied(),
+                    effective.levels.regions.get("b").copied(),
+                )
+            });
+            if a != b {
+                torn += 1;
+            }
+        }
+        stop.store(true, Ordering::Relaxed);
+        for writer in writers {
+            writer.join().expect("writer thread");
+        }
+        crate::assert_with_log!(torn == 0, "no half-applied batch observed", 0, torn);
+        crate::test_complete!("batches_publish_atomically");
+    }
+
+    #[test]
+    fn audit_trail_records_every_change() {
+        init_test("audit_trail_records_every_change");
+        let collector = LogCollector::new(64);
+        let control = collector.control().clone();
+        let event = LogScope::Event("db.".into());
+        control
+            .apply(
+                "alice",
+                &[
+                    LogChange::level(LogScope::Global, LogLevel::Debug),
+                    LogChange::sample_rate(event.clone(), 0.5)
+                        .with_ttl(Duration::from_secs(1)),
+                ],
+                at(10),
+            )
+            .expect("set");
+        control
+            .apply(
+                "bob",
+                &[LogChange::clear(event.clone(), LogSettingKind::Level)],
+                at(20),
+            )
+            .expect("clear");
+        control.expire(at(1_010));
+
+        let audit = control.audit();
+        let summary: Vec<_> = audit
+            .iter()
+            .map(|r| (r.epoch, r.actor.as_str(), r.cause, r.previous, r.current))
+            .collect();
+        let expected = vec![
+            (
+                1,
+                "alice",
+                LogControlCause::Set,
+                Some(LogSetting::Level(LogLevel::Info)),
+                Some(LogSetting::Level(LogLevel::Debug)),
+            ),
+            (1, "alice", LogControlCause::Set, None, Some(LogSetting::SampleRate(0.5))),
+            (2, "bob", LogControlCause::Clear, None, None),
+            (3, EXPIRY_ACTOR, LogControlCause::Expired, Some(LogSetting::SampleRate(0.5)), None),
+        ];
+        crate::assert_with_log!(summary == expected, "audit trail", expected, summary);
+        crate::assert_with_log!(
+            audit[3].expires_at == Some(at(1_010)),
+            "expiry deadline recorded",
+            Some(at(1_010)),
+            audit[3].expires_at
+        );
+
+        let logged = collector
+            .peek()
+            .iter()
+            .filter(|entry| entry.target() == Some(LOG_CONTROL_TARGET))
+            .count();
+        crate::assert_with_log!(logged == 4, "audit entries logged", 4, logged);
+        crate::test_complete!("audit_trail_records_every_change");
+    }
+
+    #[test]
+    fn console_wire_round_trip() {
+        init_test("console_wire_round_trip");
+        let collector = LogCollector::new(16);
+        let control = collector.control().clone();
+        let request = LogControlRequest::new(LogControlCommand::Apply {
+            actor: "console".into(),
+            changes: vec![
+                LogChange::level(LogScope::Region("orders".into()), LogLevel::Trace)
+                    .with_ttl(Duration::from_secs(30)),
+            ],
+        });
+        let json = request.to_json().expect("encode");
+        let decoded = LogControlRequest::from_json(&json).expect("decode");
+        crate::assert_with_log!(decoded == request, "request round-trips", request, decoded);
+
+        let response = control.handle_request(&decoded, at(5));
+        let response = LogControlResponse::from_json(&response.to_json().expect("encode"))
+            .expect("decode response");
+        crate::assert_with_log!(
+            response.reply == LogControlReply::Applied { epoch: 1 },
+            "applied",
+            "Applied { epoch: 1 }",
+            response.reply
+        );
+
+        let query =
+            control.handle_request(&LogControlRequest::new(LogControlCommand::Query), at(6));
+        let LogControlReply::Effective { snapshot } = query.reply else {
+            panic!("expected effective snapshot, got {:?}", query.reply);
+        };
+        crate::assert_with_log!(
+            snapshot.overrides.len() == 1 && snapshot.overrides[0].expires_at == Some(at(30_005)),
+            "query reports the override and its deadline",
+            1,
+            snapshot.overrides
+        );
+
+        let mut stale = LogControlRequest::new(LogControlCommand::Audit);
+        stale.schema_version = "asupersync.log_control_wire.v0".into();
+        let rejected = control.handle_request(&stale, at(7));
+        crate::assert_with_log!(
+            matches!(rejected.reply, LogControlReply::Rejected { .. }),
+            "unknown schema rejected",
+            "Rejected",
+            rejected.reply
+        );
+        crate::test_complete!("console_wire_round_trip");
+    }
+}
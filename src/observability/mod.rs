@@ -6,8 +6,17 @@
 //!
 //! - **Structured logging** with severity levels and rich context
 //! - **Metrics** for runtime statistics (counters, gauges, histograms)
+//! - **Exposition** of typed, labeled metrics in Prometheus / OpenMetrics
+//!   text format, with a `/metrics` handler and listener
 //! - **Diagnostic context** for hierarchical operation tracking
 //! - **Event batching** for efficient reporting
+//! - **Rate limiting** that aggregates log storms into summary entries
+//! - **Log control** that adjusts levels and sampling per region or event at
+//!   runtime, with expiring overrides and an audit trail
+//! - **Region namespaces** that label each region's telemetry and hold it to
+//!   per-region quotas
+//! - **Region lifecycle events** streamed to subscribers with filtering and
+//!   explicit lag accounting
 //! - **Configuration** for runtime observability settings
 //!
 //! # Design Principles
@@ -49,11 +58,13 @@ pub mod context;
 pub mod debt_runtime_integration;
 pub mod diagnostics;
 pub mod entry;
+pub mod exposition;
 #[cfg(test)]
 pub mod head_based_sampling_audit_test;
 #[cfg(feature = "metrics")]
 pub mod histogram_conformance;
 pub mod level;
+pub mod log_control;
 pub mod metrics;
 #[cfg(test)]
 pub mod mock_code_finder_clean_sweep_audit_test;
@@ -123,6 +134,8 @@ pub mod otlp_unexpected_status_audit_test;
 pub mod otlp_upgrade_required_audit_test;
 pub mod performance_budget_monitor;
 pub mod pressure_governor;
+pub mod rate_limit;
+pub mod region_events;
 pub mod resource_accounting;
 #[cfg(all(test, feature = "metrics"))]
 pub mod resource_attribute_merging_audit_test;
@@ -143,6 +156,7 @@ pub mod structured_cancellation_analyzer;
 pub mod subscriber_installation_order_audit_test;
 pub mod swarm_pressure_governor;
 pub mod task_inspector;
+pub mod tenant;
 #[cfg(all(test, feature = "metrics"))]
 pub mod tls_configuration_audit_test;
 #[cfg(test)]
@@ -204,7 +218,19 @@ pub use diagnostics::{
     verify_tail_latency_budget_certificate,
 };
 pub use entry::LogEntry;
+#[cfg(not(target_arch = "wasm32"))]
+pub use exposition::bind_metrics_listener;
+pub use exposition::{
+    CounterFamily, ExpositionFormat, GaugeFamily, HistogramFamily, MetricOpts, MetricsHandler,
+    MetricsRegistry, RegistryError,
+};
 pub use level::LogLevel;
+pub use log_control::{
+    AUDIT_CAPACITY, EXPIRY_ACTOR, LOG_CONTROL_TARGET, LOG_CONTROL_WIRE_SCHEMA_V1, LogChange,
+    LogControl, LogControlAudit, LogControlCause, LogControlCommand, LogControlError,
+    LogControlReply, LogControlRequest, LogControlResponse, LogControlSnapshot, LogOverride,
+    LogScope, LogSetting, LogSettingKind,
+};
 pub use metrics::{
     Counter, Gauge, Histogram, MetricValue, Metrics, MetricsProvider, NoOpMetrics, OutcomeKind,
 };
@@ -244,6 +270,16 @@ pub use pressure_governor::{
     AdmissionDecision, PressureGovernor, PressureGovernorConfig, PressureSnapshot,
     PressureThresholds,
 };
+pub use rate_limit::{
+    Admission, DEFAULT_NEVER_SUPPRESS, EventRateLimit, EventRateLimiter, RateLimitConfig,
+    RegionLimit, SPAWN_SITE_FIELD,
+};
+pub use region_events::{
+    DEFAULT_REGION_EVENT_CAPACITY, MAX_REGION_EVENT_CAPACITY, REGION_EVENTS_WIRE_SCHEMA_V1,
+    RegionCloseStatus, RegionEventBody, RegionEventDetail, RegionEventFilter, RegionEventFrame,
+    RegionEventHub, RegionEventKind, RegionEventLag, RegionEventStream, RegionEventStreamStats,
+    RegionEventSubscribeRequest, RegionLifecycleEvent,
+};
 pub use resource_accounting::{
     AdmissionKindStats, ObligationKindStats, ResourceAccounting, ResourceAccountingSnapshot,
 };
@@ -264,6 +300,10 @@ pub use task_inspector::{
     TaskInspector, TaskInspectorConfig, TaskRegionCountWire, TaskStateInfo, TaskSummary,
     TaskSummaryWire,
 };
+pub use tenant::{
+    QuotaKind, REGION_NAME_FIELD, RegionFamily, RegionScope, RegionTelemetry, TelemetryError,
+    TelemetryQuota, TelemetryUsage,
+};
 pub use w3c_trace_context::{
     SpanId as W3CSpanId, TraceContextError, TraceFlags, TraceId, W3CBaggage, W3CPropagationContext,
     W3CTraceContext, extract_baggage_from_http, extract_from_http, extract_propagation_from_http,
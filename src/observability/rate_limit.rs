@@ -0,0 +1,661 @@
+//! Per-event rate limiting for structured log entries.
+//!
+//! A task stuck in a retry loop can emit the same event millions of times
+//! and take down whatever consumes the log. An [`EventRateLimiter`] attached
+//! to a [`LogCollector`](super::LogCollector) gives every
+//! (spawn site, event name) pair its own token bucket. Entries over the
+//! limit are counted instead of recorded, and once per summary interval the
+//! collector records one summary entry carrying the count and the first and
+//! last suppressed entries, so a storm is aggregated rather than silently
+//! lost.
+//!
+//! # Keys and Limits
+//!
+//! - The *event name* is the entry's message.
+//! - The *spawn site* is the entry's [`SPAWN_SITE_FIELD`] field, falling back
+//!   to its target. Set the field on the spawning task's diagnostic context
+//!   and every entry the task logs carries it.
+//! - Limits resolve in order: event names matching the never-suppress list
+//!   are unlimited; then a region limit installed with
+//!   [`Cx::set_event_rate_limit`](crate::cx::Cx::set_event_rate_limit), which
+//!   covers the region's whole subtree with buckets of its own; then the
+//!   limiter's default.
+//!
+//! # Cost and Determinism
+//!
+//! Deciding whether to emit takes no lock. Buckets live in a fixed-size
+//! table whose slots are initialised once, and each bucket's state is a
+//! single atomic GCRA (generic cell rate algorithm) timestamp. Suppressed
+//! entries bump atomic counters and record the last sample only if its slot
+//! is uncontended. Timing comes from the caller's clock (a `Cx` passes its
+//! timer driver's time), so limits and summary windows follow virtual time
+//! under the lab runtime.
+
+use super::entry::LogEntry;
+use crate::types::{RegionId, Time};
+use parking_lot::Mutex;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Entry field naming the spawn site an entry came from.
+pub const SPAWN_SITE_FIELD: &str = "spawn_site";
+
+/// Event-name prefixes that are never suppressed by default: cancellation
+/// and obligation-leak reports must always reach the log.
+pub const DEFAULT_NEVER_SUPPRESS: &[&str] = &["cancel", "obligation leak", "obligation_leak"];
+
+const SUMMARY_TARGET: &str = "asupersync::observability::rate_limit";
+const NO_WINDOW: u64 = u64::MAX;
+const MAX_PROBES: usize = 8;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// A token-bucket limit: a sustained rate plus a burst allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventRateLimit {
+    per_second: u32,
+    burst: u32,
+}
+
+impl EventRateLimit {
+    /// No limit.
+    pub const UNLIMITED: Self = Self {
+        per_second: 0,
+        burst: 0,
+    };
+
+    /// Allows `per_second` entries per second on average, in bursts of up to
+    /// `burst`. Both are clamped to at least 1.
+    #[must_use]
+    pub const fn new(per_second: u32, burst: u32) -> Self {
+        Self {
+            per_second: if per_second == 0 { 1 } else { per_second },
+            burst: if burst == 0 { 1 } else { burst },
+        }
+    }
+
+    /// Returns `true` for [`Self::UNLIMITED`].
+    #[must_use]
+    pub const fn is_unlimited(&self) -> bool {
+        self.per_second == 0
+    }
+
+    /// Sustained entries per second, or 0 when unlimited.
+    #[must_use]
+    pub const fn per_second(&self) -> u32 {
+        self.per_second
+    }
+
+    /// Burst allowance, or 0 when unlimited.
+    #[must_use]
+    pub const fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    const fn interval_ns(self) -> u64 {
+        NANOS_PER_SEC / self.per_second as u64
+    }
+}
+
+/// A limit installed for one region's subtree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionLimit {
+    /// Region whose subtree the limit covers; also keys its buckets.
+    pub region: RegionId,
+    /// The limit.
+    pub limit: EventRateLimit,
+}
+
+/// Configuration for an [`EventRateLimiter`].
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    default_limit: EventRateLimit,
+    never_suppress: Vec<String>,
+    summary_interval: Duration,
+    max_keys: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self::new(EventRateLimit::new(100, 200))
+    }
+}
+
+impl RateLimitConfig {
+    /// Creates a configuration with `default_limit`, the
+    /// [`DEFAULT_NEVER_SUPPRESS`] list, a 10s summary interval, and room for
+    /// 1024 tracked keys.
+    #[must_use]
+    pub fn new(default_limit: EventRateLimit) -> Self {
+        Self {
+            default_limit,
+            never_suppress: DEFAULT_NEVER_SUPPRESS
+                .iter()
+                .map(|name| (*name).to_string())
+                .collect(),
+            summary_interval: Duration::from_secs(10),
+            max_keys: 1024,
+        }
+    }
+
+    /// Never suppresses events whose name starts with `prefix`
+    /// (ASCII case-insensitive).
+    #[must_use]
+    pub fn with_never_suppress(mut self, prefix: impl Into<String>) -> Self {
+        self.never_suppress.push(prefix.into());
+        self
+    }
+
+    /// Sets how often a suppressed key emits its summary.
+    #[must_use]
+    pub const fn with_summary_interval(mut self, interval: Duration) -> Self {
+        self.summary_interval = interval;
+        self
+    }
+
+    /// Sets how many distinct keys get their own bucket (at least 1). Keys
+    /// beyond that share one overflow bucket.
+    #[must_use]
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys.max(1);
+        self
+    }
+
+    /// Returns the default limit.
+    #[must_use]
+    pub const fn default_limit(&self) -> EventRateLimit {
+        self.default_limit
+    }
+
+    /// Returns the never-suppress prefixes.
+    #[must_use]
+    pub fn never_suppress(&self) -> &[String] {
+        &self.never_suppress
+    }
+
+    /// Returns the summary interval.
+    #[must_use]
+    pub const fn summary_interval(&self) -> Duration {
+        self.summary_interval
+    }
+
+    /// Returns the number of keys with their own bucket.
+    #[must_use]
+    pub const fn max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    fn never_suppresses(&self, event: &str) -> bool {
+        self.never_suppress.iter().any(|prefix| {
+            event
+                .as_bytes()
+                .get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix.as_bytes()))
+        })
+    }
+}
+
+/// Outcome of [`EventRateLimiter::admit`].
+#[derive(Debug)]
+pub struct Admission {
+    emit: bool,
+    summary: Option<LogEntry>,
+}
+
+impl Admission {
+    /// Returns `true` if the entry should be recorded.
+    #[must_use]
+    pub const fn emit(&self) -> bool {
+        self.emit
+    }
+
+    /// Takes the summary that came due for the entry's key, if any. Record
+    /// it before the entry itself.
+    #[must_use]
+    pub fn take_summary(&mut self) -> Option<LogEntry> {
+        self.summary.take()
+    }
+}
+
+/// Shared per-key rate limiter for log entries. Clones share state.
+#[derive(Debug, Clone)]
+pub struct EventRateLimiter {
+    inner: Arc<LimiterInner>,
+}
+
+#[derive(Debug)]
+struct LimiterInner {
+    config: RateLimitConfig,
+    slots: Box<[OnceLock<Box<Bucket>>]>,
+    overflow: Bucket,
+    suppressed_total: AtomicU64,
+}
+
+impl EventRateLimiter {
+    /// Creates a limiter.
+    #[must_use]
+    pub fn new(config: RateLimitConfig) -> Self {
+        let slots = (0..config.max_keys).map(|_| OnceLock::new()).collect();
+        Self {
+            inner: Arc::new(LimiterInner {
+                config,
+                slots,
+                overflow: Bucket::new(None, "*", "*"),
+                suppressed_total: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.inner.config
+    }
+
+    /// Decides whether `entry`, logged at `now` under `scope`, should be
+    /// recorded, and hands back its key's summary if one has come due.
+    #[must_use]
+    pub fn admit(&self, entry: &LogEntry, scope: Option<RegionLimit>, now: Time) -> Admission {
+        let event = entry.message();
+        let config = &self.inner.config;
+        let (region, limit) = match scope {
+            Some(scope) => (Some(scope.region), scope.limit),
+            None => (None, config.default_limit),
+        };
+        if limit.is_unlimited() || config.never_suppresses(event) {
+            return Admission {
+                emit: true,
+                summary: None,
+            };
+        }
+
+        let site = entry
+            .get_field(SPAWN_SITE_FIELD)
+            .or_else(|| entry.target())
+            .unwrap_or("");
+        let now_ns = now.as_nanos();
+        let bucket = self.inner.bucket(region, site, event);
+        let summary = bucket.take_due_summary(now_ns, config.summary_interval);
+        let emit = bucket.try_acquire(limit, now_ns);
+        if !emit {
+            bucket.record_suppressed(entry, now_ns);
+            self.inner.suppressed_total.fetch_add(1, Ordering::Relaxed);
+        }
+        Admission { emit, summary }
+    }
+
+    /// Takes every summary that has come due by `now`, including those for
+    /// keys that have gone quiet since their last suppression.
+    #[must_use]
+    pub fn take_due_summaries(&self, now: Time) -> Vec<LogEntry> {
+        let now_ns = now.as_nanos();
+        let interval = self.inner.config.summary_interval;
+        self.inner
+            .slots
+            .iter()
+            .filter_map(|slot| slot.get().map(|bucket| &**bucket))
+            .chain(std::iter::once(&self.inner.overflow))
+            .filter_map(|bucket| bucket.take_due_summary(now_ns, interval))
+            .collect()
+    }
+
+    /// Returns how many entries have been suppressed in total.
+    #[must_use]
+    pub fn suppressed_total(&self) -> u64 {
+        self.inner.suppressed_total.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many keys have their own bucket.
+    #[must_use]
+    pub fn tracked_keys(&self) -> usize {
+        self.inner
+            .slots
+            .iter()
+            .filter(|slot| slot.get().is_some())
+            .count()
+    }
+}
+
+impl LimiterInner {
+    /// Finds or claims the bucket for a key by linear probing; slots are
+    /// initialised at most once, so lookups never block.
+    fn bucket(&self, region: Option<RegionId>, site: &str, event: &str) -> &Bucket {
+        let mut hasher = DefaultHasher::new();
+        (region, site, event).hash(&mut hasher);
+        let len = self.slots.len();
+        let start = (hasher.finish() % len as u64) as usize;
+        for probe in 0..MAX_PROBES.min(len) {
+            let slot = &self.slots[(start + probe) % len];
+            let bucket = slot.get_or_init(|| Box::new(Bucket::new(region, site, event)));
+            if bucket.region == region && bucket.site == site && bucket.event == event {
+                return bucket;
+            }
+        }
+        &self.overflow
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    region: Option<RegionId>,
+    site: String,
+    event: String,
+    /// GCRA theoretical arrival time, in nanoseconds.
+    tat_ns: AtomicU64,
+    suppressed: AtomicU64,
+    /// Time of the first suppression in the open window, or `NO_WINDOW`.
+    window_start_ns: AtomicU64,
+    first_sample: Mutex<Option<LogEntry>>,
+    last_sample: Mutex<Option<LogEntry>>,
+}
+
+impl Bucket {
+    fn new(region: Option<RegionId>, site: &str, event: &str) -> Self {
+        Self {
+            region,
+            site: site.to_string(),
+            event: event.to_string(),
+            tat_ns: AtomicU64::new(0),
+            suppressed: AtomicU64::new(0),
+            window_start_ns: AtomicU64::new(NO_WINDOW),
+            first_sample: Mutex::new(None),
+            last_sample: Mutex::new(None),
+        }
+    }
+
+    fn try_acquire(&self, limit: EventRateLimit, now_ns: u64) -> bool {
+        let interval = limit.interval_ns();
+        let tolerance = interval.saturating_mul(u64::from(limit.burst));
+        let mut tat = self.tat_ns.load(Ordering::Acquire);
+        loop {
+            let next = tat.max(now_ns).saturating_add(interval);
+            if next - now_ns > tolerance {
+                return false;
+            }
+            match self
+                .tat_ns
+                .compare_exchange_weak(tat, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return true,
+                Err(current) => tat = current,
+            }
+        }
+    }
+
+    fn record_suppressed(&self, entry: &LogEntry, now_ns: u64) {
+        if self.suppressed.fetch_add(1, Ordering::AcqRel) == 0 {
+            *self.first_sample.lock() = Some(entry.clone());
+            self.window_start_ns.store(now_ns, Ordering::Release);
+        } else if let Some(mut last) = self.last_sample.try_lock() {
+            *last = Some(entry.clone());
+        }
+    }
+
+    fn take_due_summary(&self, now_ns: u64, interval: Duration) -> Option<LogEntry> {
+        let start = self.window_start_ns.load(Ordering::Acquire);
+        let interval_ns = u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX);
+        if start == NO_WINDOW || now_ns.saturating_sub(start) < interval_ns {
+            return None;
+        }
+        // Whoever closes the window reports it; samples are taken before the
+        // count so a suppression racing the close lands in the next window.
+        self.window_start_ns
+            .compare_exchange(start, NO_WINDOW, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        let first = self.first_sample.lock().take();
+        let last = self.last_sample.lock().take();
+        let suppressed = self.suppressed.swap(0, Ordering::AcqRel);
+        Some(self.summary(suppressed, now_ns, now_ns - start, first, last))
+    }
+
+    fn summary(
+        &self,
+        suppressed: u64,
+        now_ns: u64,
+        window_ns: u64,
+        first: Option<LogEntry>,
+        last: Option<LogEntry>,
+    ) -> LogEntry {
+        let window = Duration::from_nanos(window_ns);
+        let mut summary = LogEntry::warn(format!(
+            "suppressed {suppressed} occurrences of {} in the last {window:?}",
+            self.event
+        ))
+        .with_target(SUMMARY_TARGET)
+        .with_timestamp(Time::from_nanos(now_ns))
+        .with_field("event", self.event.as_str())
+        .with_field(SPAWN_SITE_FIELD, self.site.as_str())
+        .with_field("suppressed", suppressed.to_string())
+        .with_field("window_ms", window.as_millis().to_string());
+        if let Some(region) = self.region {
+            summary = summary.with_field("limit_region_id", region.to_string());
+        }
+        if let Some(first) = first {
+            summary = summary.with_field("first_sample", first.format_compact());
+        }
+        if let Some(last) = last {
+            summary = summary.with_field("last_sample", last.format_compact());
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Cx;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::observability::{LogCollector, LogLevel};
+    use crate::types::Budget;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn at(millis: u64) -> Time {
+        Time::from_millis(millis)
+    }
+
+    fn collector(config: RateLimitConfig) -> LogCollector {
+        LogCollector::new(100_000)
+            .with_min_level(LogLevel::Trace)
+            .with_rate_limiter(EventRateLimiter::new(config))
+    }
+
+    fn summaries(entries: &[LogEntry]) -> Vec<&LogEntry> {
+        entries
+            .iter()
+            .filter(|entry| entry.target() == Some(SUMMARY_TARGET))
+            .collect()
+    }
+
+    #[test]
+    fn test_suppression_starts_at_burst_and_summary_counts_match() {
+        init_test("test_suppression_starts_at_burst_and_summary_counts_match");
+        let collector = collector(RateLimitConfig::new(EventRateLimit::new(1, 3)));
+        for attempt in 0..10 {
+            collector.log(
+                LogEntry::error("connect failed")
+                    .with_field(SPAWN_SITE_FIELD, "retry_loop")
+                    .with_field("attempt", attempt.to_string())
+                    .with_timestamp(at(1_000)),
+            );
+        }
+        let recorded = collector.len();
+        crate::assert_with_log!(recorded == 3, "burst admitted", 3, recorded);
+
+        collector.flush_rate_limit_summaries(at(10_999));
+        let early = collector.len();
+        crate::assert_with_log!(early == 3, "no summary before window", 3, early);
+
+        collector.flush_rate_limit_summaries(at(11_000));
+        let entries = collector.drain();
+        let found = summaries(&entries);
+        crate::assert_with_log!(found.len() == 1, "one summary", 1, found.len());
+        let summary = found[0];
+        crate::assert_with_log!(
+            summary.message() == "suppressed 7 occurrences of connect failed in the last 10s",
+            "summary message",
+            "suppressed 7 ...",
+            summary.message()
+        );
+        let count = summary.get_field("suppressed");
+        crate::assert_with_log!(count == Some("7"), "summary count", Some("7"), count);
+        let site = summary.get_field(SPAWN_SITE_FIELD);
+        crate::assert_with_log!(site == Some("retry_loop"), "site", Some("retry_loop"), site);
+        let first = summary.get_field("first_sample").unwrap_or_default();
+        crate::assert_with_log!(first.contains("attempt=3"), "first sample", "attempt=3", first);
+        let last = summary.get_field("last_sample").unwrap_or_default();
+        crate::assert_with_log!(last.contains("attempt=9"), "last sample", "attempt=9", last);
+        crate::test_complete!("test_suppression_starts_at_burst_and_summary_counts_match");
+    }
+
+    #[test]
+    fn test_never_suppress_list_is_honored() {
+        init_test("test_never_suppress_list_is_honored");
+        let config = RateLimitConfig::new(EventRateLimit::new(1, 1))
+            .with_never_suppress("audit");
+        let collector = collector(config);
+        for _ in 0..50 {
+            collector.log(LogEntry::warn("Cancel requested").with_timestamp(at(1)));
+            collector.log(LogEntry::error("obligation leak detected").with_timestamp(at(1)));
+            collector.log(LogEntry::info("audit: key rotated").with_timestamp(at(1)));
+            collector.log(LogEntry::info("noisy").with_timestamp(at(1)));
+        }
+        let recorded = collector.len();
+        crate::assert_with_log!(recorded == 151, "critical events kept", 151, recorded);
+        let suppressed = collector
+            .rate_limiter()
+            .map(EventRateLimiter::suppressed_total);
+        crate::assert_with_log!(suppressed == Some(49), "only noisy", Some(49), suppressed);
+        crate::test_complete!("test_never_suppress_list_is_honored");
+    }
+
+    #[test]
+    fn test_region_limit_takes_precedence_over_default() {
+        init_test("test_region_limit_takes_precedence_over_default");
+        let limiter = EventRateLimiter::new(RateLimitConfig::new(EventRateLimit::new(1, 5)));
+        let tenant = RegionLimit {
+            region: RegionId::new_for_test(7, 0),
+            limit: EventRateLimit::new(1, 2),
+        };
+        let trusted = RegionLimit {
+            region: RegionId::new_for_test(8, 0),
+            limit: EventRateLimit::UNLIMITED,
+        };
+        let count = |scope: Option<RegionLimit>, message: &str| {
+            (0..20)
+                .filter(|_| limiter.admit(&LogEntry::info(message), scope, at(1)).emit())
+                .count()
+        };
+        let tenant_emitted = count(Some(tenant), "query failed");
+        let default_emitted = count(None, "query failed");
+        let trusted_emitted = count(Some(trusted), "query failed");
+        let critical_emitted = count(Some(tenant), "cancelled by parent");
+        crate::assert_with_log!(tenant_emitted == 2, "tenant limit", 2, tenant_emitted);
+        crate::assert_with_log!(default_emitted == 5, "own bucket", 5, default_emitted);
+        crate::assert_with_log!(trusted_emitted == 20, "unlimited", 20, trusted_emitted);
+        crate::assert_with_log!(critical_emitted == 20, "never", 20, critical_emitted);
+        crate::test_complete!("test_region_limit_takes_precedence_over_default");
+    }
+
+    fn run_retry_storm(seed: u64) -> Vec<(u64, String)> {
+        let config = RateLimitConfig::new(EventRateLimit::new(1, 1))
+            .with_summary_interval(Duration::from_secs(2));
+        let collector = collector(config);
+        let task_collector = collector.clone();
+        let mut lab = LabRuntime::new(LabConfig::new(seed).with_auto_advance());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (task, _handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("task Cx");
+                cx.set_log_collector(task_collector);
+                for _ in 0..50 {
+                    cx.log(LogEntry::error("retry failed"));
+                    crate::time::sleep(crate::time::wall_now(), Duration::from_millis(100)).await;
+                }
+            })
+            .expect("create storm task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_with_auto_advance();
+        collector
+            .drain()
+            .iter()
+            .map(|entry| (entry.timestamp().as_millis(), entry.message().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_summary_timing_under_virtual_time() {
+        init_test("test_summary_timing_under_virtual_time");
+        let entries = run_retry_storm(3);
+        let summaries: Vec<_> = entries
+            .iter()
+            .filter(|(_, message)| message.starts_with("suppressed"))
+            .cloned()
+            .collect();
+        let expected = vec![
+            (
+                2_100,
+                "suppressed 18 occurrences of retry failed in the last 2s".to_string(),
+            ),
+            (
+                4_100,
+                "suppressed 18 occurrences of retry failed in the last 2s".to_string(),
+            ),
+        ];
+        crate::assert_with_log!(summaries == expected, "summaries", expected, summaries);
+        let emitted = entries.len() - summaries.len();
+        crate::assert_with_log!(emitted == 5, "one per virtual second", 5, emitted);
+        let replay = run_retry_storm(3);
+        crate::assert_with_log!(replay == entries, "deterministic", entries, replay);
+        crate::test_complete!("test_summary_timing_under_virtual_time");
+    }
+
+    #[test]
+    fn test_concurrent_suppression_loses_no_increments() {
+        init_test("test_concurrent_suppression_loses_no_increments");
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 10_000;
+        let collector = collector(RateLimitConfig::new(EventRateLimit::new(1, 100)));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let collector = collector.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..PER_THREAD {
+                        collector.log(LogEntry::error("hot loop").with_timestamp(at(1_000)));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("logging thread");
+        }
+        let emitted = collector.len();
+        crate::assert_with_log!(emitted == 100, "burst emitted", 100, emitted);
+        let limiter = collector.rate_limiter().expect("limiter").clone();
+        let suppressed = limiter.suppressed_total();
+        let expected = (THREADS * PER_THREAD - 100) as u64;
+        crate::assert_with_log!(suppressed == expected, "total", expected, suppressed);
+
+        let summaries = limiter.take_due_summaries(at(11_000));
+        let counted: u64 = summaries
+            .iter()
+            .filter_map(|summary| summary.get_field("suppressed"))
+            .map(|count| count.parse::<u64>().expect("numeric count"))
+            .sum();
+        crate::assert_with_log!(counted == expected, "summary total", expected, counted);
+        crate::test_complete!("test_concurrent_suppression_loses_no_increments");
+    }
+}
@@ -0,0 +1,1012 @@
+//! Region lifecycle event stream.
+//!
+//! The runtime publishes one [`RegionLifecycleEvent`] at each milestone in a
+//! region's life: created, first task spawned, cancel requested, drain
+//! started and closed. External orchestration (autoscalers, dashboards)
+//! subscribes through [`RegionEventHub::subscribe`] and receives a
+//! [`RegionEventStream`] instead of scraping logs.
+//!
+//! # Filtering
+//!
+//! Each subscription carries a [`RegionEventFilter`] on region label prefix
+//! and event kind. Filters are evaluated when an event is published, before
+//! it is queued, so a narrow subscription costs nothing for the events it
+//! rejects.
+//!
+//! # Lagging
+//!
+//! Every subscriber owns a bounded queue. Publishing never blocks: when a
+//! queue is full its oldest event is dropped and counted. The next poll of
+//! the stream yields a [`RegionEventLag`] carrying the number of events lost
+//! before it resumes with the oldest retained one, so loss is always
+//! visible and always counted. One slow subscriber never affects the runtime
+//! or any other subscriber.
+//!
+//! # Cost
+//!
+//! With no subscriber the runtime's lifecycle hooks stop at a single relaxed
+//! atomic load; no event is built and no lock is taken.
+//!
+//! # Console protocol
+//!
+//! [`RegionEventSubscribeRequest`] and [`RegionEventFrame`] are the JSON wire
+//! format, versioned by [`REGION_EVENTS_WIRE_SCHEMA_V1`];
+//! [`RegionEventHub::subscribe_console`] serves the request and
+//! [`RegionEventStream::try_next_frame`] produces the frames.
+
+use crate::record::RegionTaskCounts;
+use crate::stream::Stream;
+use crate::types::{CancelKind, RegionId, Severity, TaskId, Time};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+
+/// Stable schema identifier for region-event console messages.
+pub const REGION_EVENTS_WIRE_SCHEMA_V1: &str = "asupersync.region_events_wire.v1";
+
+/// Queue capacity of a subscription that does not ask for one.
+pub const DEFAULT_REGION_EVENT_CAPACITY: usize = 1024;
+
+/// Largest queue capacity a console subscription may request.
+pub const MAX_REGION_EVENT_CAPACITY: usize = 65_536;
+
+/// Lifecycle milestone an event reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionEventKind {
+    /// The region was created.
+    Created,
+    /// The region admitted its first task.
+    FirstTaskSpawned,
+    /// Cancellation was requested for the region.
+    CancelRequested,
+    /// The region stopped accepting work and began draining its children.
+    DrainStarted,
+    /// The region closed.
+    Closed,
+}
+
+impl RegionEventKind {
+    /// Every kind, in lifecycle order.
+    pub const ALL: [Self; 5] = [
+        Self::Created,
+        Self::FirstTaskSpawned,
+        Self::CancelRequested,
+        Self::DrainStarted,
+        Self::Closed,
+    ];
+
+    /// Returns the wire name of this kind.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::FirstTaskSpawned => "first_task_spawned",
+            Self::CancelRequested => "cancel_requested",
+            Self::DrainStarted => "drain_started",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for RegionEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Aggregate outcome of a closed region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionCloseStatus {
+    /// Every task succeeded, or the region ran none.
+    Ok,
+    /// At least one task failed with an error.
+    Err,
+    /// The region's work was cancelled.
+    Cancelled,
+    /// At least one task panicked.
+    Panicked,
+}
+
+impl RegionCloseStatus {
+    /// Maps an outcome severity to a close status.
+    #[must_use]
+    pub const fn from_severity(severity: Severity) -> Self {
+        match severity {
+            Severity::Ok => Self::Ok,
+            Severity::Err => Self::Err,
+            Severity::Cancelled => Self::Cancelled,
+            Severity::Panicked => Self::Panicked,
+        }
+    }
+}
+
+/// Kind-specific payload of a [`RegionLifecycleEvent`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum RegionEventDetail {
+    /// The region was created.
+    Created {
+        /// Parent region, or `None` for a root.
+        parent: Option<RegionId>,
+    },
+    /// The region admitted its first task.
+    FirstTaskSpawned {
+        /// The task.
+        task: TaskId,
+    },
+    /// Cancellation was requested for the region.
+    CancelRequested {
+        /// Kind of the cancellation.
+        cancel_kind: CancelKind,
+        /// Region the cancellation originated in.
+        origin_region: RegionId,
+        /// Task that requested it, if any.
+        origin_task: Option<TaskId>,
+    },
+    /// The region stopped accepting work and began draining its children.
+    DrainStarted,
+    /// The region closed.
+    Closed {
+        /// Aggregate outcome of the region's tasks.
+        status: RegionCloseStatus,
+        /// Logical time from creation to close, in nanoseconds.
+        lifetime_nanos: u64,
+        /// Tasks the region admitted and how they finished.
+        tasks: RegionTaskCounts,
+    },
+}
+
+impl RegionEventDetail {
+    /// Returns the kind of this payload.
+    #[must_use]
+    pub const fn kind(&self) -> RegionEventKind {
+        match self {
+            Self::Created { .. } => RegionEventKind::Created,
+            Self::FirstTaskSpawned { .. } => RegionEventKind::FirstTaskSpawned,
+            Self::CancelRequested { .. } => RegionEventKind::CancelRequested,
+            Self::DrainStarted => RegionEventKind::DrainStarted,
+            Self::Closed { .. } => RegionEventKind::Closed,
+        }
+    }
+}
+
+/// One region lifecycle milestone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionLifecycleEvent {
+    /// Publication sequence number, increasing across the runtime.
+    pub seq: u64,
+    /// Logical time of the milestone.
+    pub at: Time,
+    /// The region.
+    pub region: RegionId,
+    /// The region's class label, if it has one.
+    pub label: Option<String>,
+    /// What happened.
+    pub detail: RegionEventDetail,
+}
+
+impl RegionLifecycleEvent {
+    /// Returns the kind of this event.
+    #[must_use]
+    pub const fn kind(&self) -> RegionEventKind {
+        self.detail.kind()
+    }
+}
+
+/// Selects the events a subscription receives.
+///
+/// The default filter accepts everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionEventFilter {
+    /// Only regions whose label starts with this prefix. Unlabeled regions
+    /// never match a prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_prefix: Option<String>,
+    /// Only these kinds; empty accepts every kind.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<RegionEventKind>,
+}
+
+impl RegionEventFilter {
+    /// Accepts every event.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the filter to regions whose label starts with `prefix`.
+    #[must_use]
+    pub fn with_label_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.label_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Restricts the filter to the given kinds.
+    #[must_use]
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = RegionEventKind>) -> Self {
+        self.kinds = kinds.into_iter().collect();
+        self
+    }
+
+    /// Returns true if an event of `kind` for a region labeled `label`
+    /// passes the filter.
+    #[must_use]
+    pub fn matches(&self, kind: RegionEventKind, label: Option<&str>) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&kind) {
+            return false;
+        }
+        match &self.label_prefix {
+            Some(prefix) => label.is_some_and(|label| label.starts_with(prefix.as_str())),
+            None => true,
+        }
+    }
+}
+
+/// A subscriber fell behind and lost events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionEventLag {
+    /// Events dropped since the subscriber last read.
+    pub missed: u64,
+}
+
+impl fmt::Display for RegionEventLag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "region event subscriber lagged, {} events missed", self.missed)
+    }
+}
+
+impl std::error::Error for RegionEventLag {}
+
+/// Delivery counters of one subscription.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionEventStreamStats {
+    /// Events handed to the reader.
+    pub delivered: u64,
+    /// Events dropped because the queue was full.
+    pub lagged: u64,
+    /// Events waiting in the queue.
+    pub queued: usize,
+}
+
+#[derive(Debug, Default)]
+struct SubscriberQueue {
+    events: VecDeque<RegionLifecycleEvent>,
+    /// Dropped events not yet reported to the reader.
+    missed: u64,
+    lagged: u64,
+    delivered: u64,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    filter: RegionEventFilter,
+    capacity: usize,
+    queue: Mutex<SubscriberQueue>,
+}
+
+impl Subscriber {
+    fn push(&self, event: RegionLifecycleEvent) {
+        let mut queue = self.queue.lock();
+        if queue.events.len() >= self.capacity {
+            queue.events.pop_front();
+            queue.missed += 1;
+            queue.lagged += 1;
+        }
+        queue.events.push_back(event);
+        let waker = queue.waker.take();
+        drop(queue);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn close(&self) {
+        let mut queue = self.queue.lock();
+        queue.closed = true;
+        let waker = queue.waker.take();
+        drop(queue);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn poll_item(
+        &self,
+        waker: Option<&Waker>,
+    ) -> Poll<Option<Result<RegionLifecycleEvent, RegionEventLag>>> {
+        let mut queue = self.queue.lock();
+        if queue.missed > 0 {
+            let missed = std::mem::take(&mut queue.missed);
+            return Poll::Ready(Some(Err(RegionEventLag { missed })));
+        }
+        if let Some(event) = queue.events.pop_front() {
+            queue.delivered += 1;
+            return Poll::Ready(Some(Ok(event)));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        if let Some(waker) = waker {
+            match &mut queue.waker {
+                Some(existing) if existing.will_wake(waker) => {}
+                slot => *slot = Some(waker.clone()),
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Fan-out point for region lifecycle events.
+///
+/// Each runtime owns one hub; see
+/// [`RuntimeState::region_events`](crate::runtime::RuntimeState::region_events).
+#[derive(Debug, Default)]
+pub struct RegionEventHub {
+    subscribers: Mutex<Vec<Arc<Subscriber>>>,
+    active: AtomicUsize,
+    published: AtomicU64,
+}
+
+impl RegionEventHub {
+    /// Creates a hub with no subscribers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if at least one subscription is open.
+    #[inline]
+    #[must_use]
+    pub fn has_subscribers(&self) -> bool {
+        self.active.load(Ordering::Relaxed) != 0
+    }
+
+    /// Returns the number of open subscriptions.
+    #[must_use]
+    pub fn subscriber_count(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of events queued for at least one subscriber.
+    #[must_use]
+    pub fn published(&self) -> u64 {
+        self.published.load(Ordering::Relaxed)
+    }
+
+    /// Opens a subscription with the default queue capacity.
+    #[must_use]
+    pub fn subscribe(self: &Arc<Self>, filter: RegionEventFilter) -> RegionEventStream {
+        self.subscribe_with_capacity(filter, DEFAULT_REGION_EVENT_CAPACITY)
+    }
+
+    /// Opens a subscription whose queue holds at most `capacity` events
+    /// (at least one).
+    #[must_use]
+    pub fn subscribe_with_capacity(
+        self: &Arc<Self>,
+        filter: RegionEventFilter,
+        capacity: usize,
+    ) -> RegionEventStream {
+        let subscriber = Arc::new(Subscriber {
+            filter,
+            capacity: capacity.max(1),
+            queue: Mutex::new(SubscriberQueue::default()),
+        });
+        let mut subscribers = self.subscribers.lock();
+        subscribers.push(Arc::clone(&subscriber));
+        self.active.store(subscribers.len(), Ordering::Relaxed);
+        drop(subscribers);
+        RegionEventStream {
+            hub: Arc::downgrade(self),
+            subscriber,
+        }
+    }
+
+    /// Opens a subscription for a console request.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RegionEventBody::Rejected`] frame, ready to send back,
+    /// when the request carries an unknown schema version.
+    pub fn subscribe_console(
+        self: &Arc<Self>,
+        request: &RegionEventSubscribeRequest,
+    ) -> Result<RegionEventStream, RegionEventFrame> {
+        if !request.has_expected_schema() {
+            return Err(RegionEventFrame::new(RegionEventBody::Rejected {
+                reason: format!("unsupported schema version {}", request.schema_version),
+            }));
+        }
+        let capacity = request
+            .capacity
+            .unwrap_or(DEFAULT_REGION_EVENT_CAPACITY)
+            .min(MAX_REGION_EVENT_CAPACITY);
+        Ok(self.subscribe_with_capacity(request.filter.clone(), capacity))
+    }
+
+    /// Publishes an event to every subscriber whose filter accepts it.
+    ///
+    /// Callers should check [`has_subscribers`](Self::has_subscribers)
+    /// before gathering the event's details.
+    pub(crate) fn publish(
+        &self,
+        at: Time,
+        region: RegionId,
+        label: Option<&str>,
+        detail: RegionEventDetail,
+    ) {
+        let kind = detail.kind();
+        let subscribers = self.subscribers.lock();
+        let mut matching = subscribers
+            .iter()
+            .filter(|subscriber| subscriber.filter.matches(kind, label))
+            .peekable();
+        if matching.peek().is_none() {
+            return;
+        }
+        let event = RegionLifecycleEvent {
+            seq: self.published.fetch_add(1, Ordering::Relaxed) + 1,
+            at,
+            region,
+            label: label.map(str::to_owned),
+            detail,
+        };
+        for subscriber in matching {
+            subscriber.push(event.clone());
+        }
+    }
+
+    fn unsubscribe(&self, subscriber: &Arc<Subscriber>) {
+        let mut subscribers = self.subscribers.lock();
+        subscribers.retain(|existing| !Arc::ptr_eq(existing, subscriber));
+        self.active.store(subscribers.len(), Ordering::Relaxed);
+    }
+}
+
+impl Drop for RegionEventHub {
+    fn drop(&mut self) {
+        for subscriber in self.subscribers.get_mut().drain(..) {
+            subscriber.close();
+        }
+    }
+}
+
+/// A subscription to region lifecycle events.
+///
+/// Yields `Ok(event)` in publication order, or `Err(lag)` when events were
+/// dropped because the subscription fell behind. Ends once the runtime
+/// that owns the hub is dropped. Dropping the stream unsubscribes.
+#[derive(Debug)]
+pub struct RegionEventStream {
+    hub: Weak<RegionEventHub>,
+    subscriber: Arc<Subscriber>,
+}
+
+impl RegionEventStream {
+    /// Returns the next queued item without waiting, or `None` when the
+    /// queue is empty.
+    pub fn try_next(&mut self) -> Option<Result<RegionLifecycleEvent, RegionEventLag>> {
+        match self.subscriber.poll_item(None) {
+            Poll::Ready(item) => item,
+            Poll::Pending => None,
+        }
+    }
+
+    /// Returns the next queued item as a console frame without waiting.
+    ///
+    /// Returns a [`RegionEventBody::Closed`] frame once the runtime is gone
+    /// and the queue is drained, and `None` while the queue is merely empty.
+    pub fn try_next_frame(&mut self) -> Option<RegionEventFrame> {
+        let body = match self.subscriber.poll_item(None) {
+            Poll::Ready(Some(Ok(event))) => RegionEventBody::Event { event },
+            Poll::Ready(Some(Err(lag))) => RegionEventBody::Lagged { missed: lag.missed },
+            Poll::Ready(None) => RegionEventBody::Closed,
+            Poll::Pending => return None,
+        };
+        Some(RegionEventFrame::new(body))
+    }
+
+    /// Returns the filter this subscription was opened with.
+    #[must_use]
+    pub fn filter(&self) -> &RegionEventFilter {
+        &self.subscriber.filter
+    }
+
+    /// Returns the delivery counters of this subscription.
+    #[must_use]
+    pub fn stats(&self) -> RegionEventStreamStats {
+        let queue = self.subscriber.queue.lock();
+        RegionEventStreamStats {
+            delivered: queue.delivered,
+            lagged: queue.lagged,
+            queued: queue.events.len(),
+        }
+    }
+}
+
+impl Stream for RegionEventStream {
+    type Item = Result<RegionLifecycleEvent, RegionEventLag>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.subscriber.poll_item(Some(cx.waker()))
+    }
+}
+
+impl Drop for RegionEventStream {
+    fn drop(&mut self) {
+        if let Some(hub) = self.hub.upgrade() {
+            hub.unsubscribe(&self.subscriber);
+        }
+    }
+}
+
+/// Wire request opening a region-event subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionEventSubscribeRequest {
+    /// Schema version identifier.
+    pub schema_version: String,
+    /// Events to receive.
+    #[serde(default)]
+    pub filter: RegionEventFilter,
+    /// Queue capacity; defaults to [`DEFAULT_REGION_EVENT_CAPACITY`] and is
+    /// capped at [`MAX_REGION_EVENT_CAPACITY`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<usize>,
+}
+
+impl RegionEventSubscribeRequest {
+    /// Wraps `filter` in the current schema version.
+    #[must_use]
+    pub fn new(filter: RegionEventFilter) -> Self {
+        Self {
+            schema_version: REGION_EVENTS_WIRE_SCHEMA_V1.to_string(),
+            filter,
+            capacity: None,
+        }
+    }
+
+    /// Sets the queue capacity.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Returns true when the payload schema matches the expected version.
+    #[must_use]
+    pub fn has_expected_schema(&self) -> bool {
+        self.schema_version == REGION_EVENTS_WIRE_SCHEMA_V1
+    }
+
+    /// Encode request as compact JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when serialization fails.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Decode request from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when parsing fails.
+    pub fn from_json(payload: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+/// Content of a [`RegionEventFrame`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "frame")]
+pub enum RegionEventBody {
+    /// A lifecycle event.
+    Event {
+        /// The event.
+        event: RegionLifecycleEvent,
+    },
+    /// Events were dropped because the subscriber fell behind.
+    Lagged {
+        /// Number of events dropped.
+        missed: u64,
+    },
+    /// The runtime shut down; no further frames follow.
+    Closed,
+    /// The subscription request was refused.
+    Rejected {
+        /// Why it was refused.
+        reason: String,
+    },
+}
+
+/// Wire frame streamed to a console subscriber.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionEventFrame {
+    /// Schema version identifier.
+    pub schema_version: String,
+    /// The frame content.
+    pub body: RegionEventBody,
+}
+
+impl RegionEventFrame {
+    /// Wraps `body` in the current schema version.
+    #[must_use]
+    pub fn new(body: RegionEventBody) -> Self {
+        Self {
+            schema_version: REGION_EVENTS_WIRE_SCHEMA_V1.to_string(),
+            body,
+        }
+    }
+
+    /// Returns true when the payload schema matches the expected version.
+    #[must_use]
+    pub fn has_expected_schema(&self) -> bool {
+        self.schema_version == REGION_EVENTS_WIRE_SCHEMA_V1
+    }
+
+    /// Encode frame as compact JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when serialization fails.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Decode frame from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `serde_json::Error` when parsing fails.
+    pub fn from_json(payload: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::LabRuntime;
+    use crate::types::{Budget, CancelReason};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn region(index: u32) -> RegionId {
+        RegionId::new_for_test(index, 0)
+    }
+
+    fn publish_created(hub: &RegionEventHub, index: u32, label: Option<&str>) {
+        let detail = RegionEventDetail::Created { parent: None };
+        let at = Time::from_millis(u64::from(index));
+        hub.publish(at, region(index), label, detail);
+    }
+
+    fn drain(stream: &mut RegionEventStream) -> Vec<Result<RegionLifecycleEvent, RegionEventLag>> {
+        std::iter::from_fn(|| stream.try_next()).collect()
+    }
+
+    fn spawn_cancel_aware(runtime: &mut LabRuntime, region: RegionId) -> TaskId {
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async {
+                std::future::poll_fn(|_| {
+                    if crate::cx::Cx::with_current(|cx| cx.checkpoint().is_err()).unwrap_or(false) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await;
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+        task_id
+    }
+
+    #[test]
+    fn lab_lifecycle_is_observed_in_order() {
+        init_test("lab_lifecycle_is_observed_in_order");
+        let mut runtime = LabRuntime::with_seed(7);
+        let mut stream = runtime
+            .state
+            .subscribe_region_events(RegionEventFilter::all());
+
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let child = runtime
+            .state
+            .create_labeled_child_region(root, Budget::INFINITE, "worker.ingest")
+            .expect("create child region");
+        let first = spawn_cancel_aware(&mut runtime, child);
+        spawn_cancel_aware(&mut runtime, child);
+        runtime.run_until_idle();
+
+        let reason = CancelReason::shutdown();
+        let (tasks, wakes) = runtime
+            .state
+            .cancel_request(child, &reason, None)
+            .into_parts();
+        {
+            let mut scheduler = runtime.scheduler.lock();
+            for (task, priority) in tasks {
+                scheduler.schedule_cancel(task, priority);
+            }
+        }
+        wakes.dispatch();
+        runtime.run_until_quiescent();
+
+        let events: Vec<_> = drain(&mut stream)
+            .into_iter()
+            .map(|item| item.expect("no lag"))
+            .collect();
+        let kinds: Vec<_> = events.iter().map(|e| (e.region, e.kind())).collect();
+        let expected = vec![
+            (root, RegionEventKind::Created),
+            (child, RegionEventKind::Created),
+            (child, RegionEventKind::FirstTaskSpawned),
+            (child, RegionEventKind::CancelRequested),
+            (child, RegionEventKind::DrainStarted),
+            (child, RegionEventKind::Closed),
+        ];
+        crate::assert_with_log!(kinds == expected, "lifecycle order", expected, kinds);
+
+        let seqs: Vec<_> = events.iter().map(|e| e.seq).collect();
+        crate::assert_with_log!(seqs == [1, 2, 3, 4, 5, 6], "sequence", "1..=6", seqs);
+        assert_eq!(events[0].label, None);
+        assert!(
+            events[1..]
+                .iter()
+                .all(|e| e.label.as_deref() == Some("worker.ingest"))
+        );
+        assert_eq!(
+            events[1].detail,
+            RegionEventDetail::Created { parent: Some(root) }
+        );
+        assert_eq!(
+            events[2].detail,
+            RegionEventDetail::FirstTaskSpawned { task: first }
+        );
+        assert_eq!(
+            events[3].detail,
+            RegionEventDetail::CancelRequested {
+                cancel_kind: reason.kind,
+                origin_region: reason.origin_region,
+                origin_task: None,
+            }
+        );
+        let RegionEventDetail::Closed { status, tasks, .. } = &events[5].detail else {
+            panic!("expected closed event, got {:?}", events[5].detail);
+        };
+        crate::assert_with_log!(
+            *status == RegionCloseStatus::Cancelled,
+            "close status",
+            RegionCloseStatus::Cancelled,
+            status
+        );
+        assert_eq!(tasks.spawned, 2);
+        assert_eq!(tasks.cancelled, 2);
+        assert_eq!(tasks.live(), 0);
+        crate::test_complete!("lab_lifecycle_is_observed_in_order");
+    }
+
+    #[test]
+    fn zero_subscribers_publish_nothing() {
+        init_test("zero_subscribers_publish_nothing");
+        let mut runtime = LabRuntime::with_seed(8);
+        let hub = Arc::clone(runtime.state.region_events());
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        for _ in 0..16 {
+            let child = runtime
+                .state
+                .create_child_region(root, Budget::INFINITE)
+                .expect("create child region");
+            spawn_cancel_aware(&mut runtime, child);
+            let (tasks, wakes) = runtime
+                .state
+                .cancel_request(child, &CancelReason::shutdown(), None)
+                .into_parts();
+            {
+                let mut scheduler = runtime.scheduler.lock();
+                for (task, priority) in tasks {
+                    scheduler.schedule_cancel(task, priority);
+                }
+            }
+            wakes.dispatch();
+            runtime.run_until_quiescent();
+        }
+
+        // The guard that keeps the runtime hooks free: nothing is built or
+        // sequenced while nobody listens.
+        assert!(!hub.has_subscribers());
+        crate::assert_with_log!(hub.published() == 0, "published", 0, hub.published());
+
+        let mut late = hub.subscribe(RegionEventFilter::all());
+        runtime
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("create child region");
+        let events = drain(&mut late);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_ref().expect("event").seq, 1);
+        crate::test_complete!("zero_subscribers_publish_nothing");
+    }
+
+    #[test]
+    fn filters_apply_before_enqueue() {
+        init_test("filters_apply_before_enqueue");
+        let hub = Arc::new(RegionEventHub::new());
+        let mut workers = hub.subscribe(RegionEventFilter::all().with_label_prefix("worker."));
+        let mut closes =
+            hub.subscribe(RegionEventFilter::all().with_kinds([RegionEventKind::Closed]));
+
+        publish_created(&hub, 1, Some("worker.a"));
+        publish_created(&hub, 2, Some("web.a"));
+        publish_created(&hub, 3, None);
+        hub.publish(
+            Time::from_millis(4),
+            region(2),
+            Some("web.a"),
+            RegionEventDetail::Closed {
+                status: RegionCloseStatus::Ok,
+                lifetime_nanos: 4,
+                tasks: RegionTaskCounts::default(),
+            },
+        );
+
+        let seen: Vec<_> = drain(&mut workers)
+            .into_iter()
+            .map(|item| item.expect("event").region)
+            .collect();
+        crate::assert_with_log!(seen == [region(1)], "prefix filter", [region(1)], seen);
+        let seen: Vec<_> = drain(&mut closes)
+            .into_iter()
+            .map(|item| item.expect("event").kind())
+            .collect();
+        assert_eq!(seen, [RegionEventKind::Closed]);
+
+        // Region 3's event matched no subscriber, so it was never sequenced.
+        assert_eq!(hub.published(), 2);
+        assert_eq!(workers.stats().queued, 0);
+        crate::test_complete!("filters_apply_before_enqueue");
+    }
+
+    #[test]
+    fn lagging_subscriber_counts_every_dropped_event() {
+        init_test("lagging_subscriber_counts_every_dropped_event");
+        let hub = Arc::new(RegionEventHub::new());
+        let mut slow = hub.subscribe_with_capacity(RegionEventFilter::all(), 3);
+
+        for index in 1..=10 {
+            publish_created(&hub, index, None);
+        }
+
+        let items = drain(&mut slow);
+        crate::assert_with_log!(
+            items[0] == Err(RegionEventLag { missed: 7 }),
+            "lag reported first",
+            "missed 7",
+            items[0]
+        );
+        let retained: Vec<_> = items[1..]
+            .iter()
+            .map(|item| item.as_ref().expect("event").seq)
+            .collect();
+        assert_eq!(retained, [8, 9, 10]);
+        assert_eq!(
+            slow.stats(),
+            RegionEventStreamStats {
+                delivered: 3,
+                lagged: 7,
+                queued: 0,
+            }
+        );
+
+        // Once reported, the lag does not repeat.
+        publish_created(&hub, 11, None);
+        assert_eq!(drain(&mut slow).len(), 1);
+        crate::test_complete!("lagging_subscriber_counts_every_dropped_event");
+    }
+
+    #[test]
+    fn subscribers_are_independent() {
+        init_test("subscribers_are_independent");
+        let hub = Arc::new(RegionEventHub::new());
+        let mut slow = hub.subscribe_with_capacity(RegionEventFilter::all(), 1);
+        let mut fast = hub.subscribe(RegionEventFilter::all());
+        assert_eq!(hub.subscriber_count(), 2);
+
+        for index in 1..=4 {
+            publish_created(&hub, index, None);
+            assert!(fast.try_next().expect("queued").is_ok());
+        }
+        assert_eq!(fast.stats().lagged, 0);
+        assert_eq!(fast.stats().delivered, 4);
+        assert_eq!(slow.stats().lagged, 3);
+
+        drop(fast);
+        assert_eq!(hub.subscriber_count(), 1);
+        publish_created(&hub, 5, None);
+        let items = drain(&mut slow);
+        assert_eq!(items[0], Err(RegionEventLag { missed: 4 }));
+        assert_eq!(items[1].as_ref().expect("event").region, region(5));
+        crate::test_complete!("subscribers_are_independent");
+    }
+
+    #[test]
+    fn stream_wakes_and_ends_with_hub() {
+        init_test("stream_wakes_and_ends_with_hub");
+        let hub = Arc::new(RegionEventHub::new());
+        let mut stream = hub.subscribe(RegionEventFilter::all());
+        let waker = std::task::Waker::noop();
+        let mut cx = Context::from_waker(waker);
+
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+        publish_created(&hub, 1, None);
+        let polled = Pin::new(&mut stream).poll_next(&mut cx);
+        assert!(matches!(polled, Poll::Ready(Some(Ok(_)))));
+
+        drop(hub);
+        let polled = Pin::new(&mut stream).poll_next(&mut cx);
+        assert!(matches!(polled, Poll::Ready(None)));
+        let frame = stream.try_next_frame().expect("closed frame");
+        assert_eq!(frame.body, RegionEventBody::Closed);
+        crate::test_complete!("stream_wakes_and_ends_with_hub");
+    }
+
+    #[test]
+    fn console_protocol_round_trips() {
+        init_test("console_protocol_round_trips");
+        let hub = Arc::new(RegionEventHub::new());
+        let request = RegionEventSubscribeRequest::new(
+            RegionEventFilter::all()
+                .with_label_prefix("worker.")
+                .with_kinds([RegionEventKind::Created, RegionEventKind::Closed]),
+        )
+        .with_capacity(2);
+        let json = request.to_json().expect("encode request");
+        let decoded = RegionEventSubscribeRequest::from_json(&json).expect("decode request");
+        assert_eq!(decoded, request);
+        assert!(decoded.has_expected_schema());
+
+        let mut stream = hub.subscribe_console(&decoded).expect("subscribe");
+        for index in 1..=3 {
+            publish_created(&hub, index, Some("worker.a"));
+        }
+        let lagged = stream.try_next_frame().expect("lag frame");
+        assert_eq!(lagged.body, RegionEventBody::Lagged { missed: 1 });
+        let frame = stream.try_next_frame().expect("event frame");
+        let json = frame.to_json().expect("encode frame");
+        assert!(json.contains("\"frame\":\"event\""), "{json}");
+        assert!(json.contains("\"kind\":\"created\""), "{json}");
+        let decoded = RegionEventFrame::from_json(&json).expect("decode frame");
+        assert_eq!(decoded, frame);
+        assert!(decoded.has_expected_schema());
+
+        let mut stale = request;
+        stale.schema_version = "asupersync.region_events_wire.v0".to_string();
+        let rejected = hub.subscribe_console(&stale).expect_err("rejected");
+        assert!(matches!(rejected.body, RegionEventBody::Rejected { .. }));
+        assert_eq!(hub.subscriber_count(), 1);
+        crate::test_complete!("console_protocol_round_trips");
+    }
+}
@@ -0,0 +1,1022 @@
+//! Per-region telemetry namespaces and quotas.
+//!
+//! Regions that host untrusted or independently operated work share one
+//! metrics registry and one log pipeline, so a single misbehaving tenant
+//! can crowd out everyone else's telemetry. [`RegionTelemetry`] gives each
+//! registered region a namespace and a budget:
+//!
+//! - **Namespacing**: families registered through a [`RegionScope`] carry a
+//!   leading [`REGION_LABEL`] label set to the region's configured name
+//!   (never its raw id, which is reused across runs), and log entries whose
+//!   context names the region are stamped with [`REGION_NAME_FIELD`].
+//! - **Series quota**: once a region holds its maximum of distinct label
+//!   sets across its families, new label sets fold into a per-region
+//!   overflow series whose non-region labels are
+//!   [`OVERFLOW_LABEL_VALUE`](super::exposition::OVERFLOW_LABEL_VALUE).
+//!   Other regions are unaffected.
+//! - **Event quota**: a region's events-per-second limit is handed to the
+//!   collector's [`EventRateLimiter`](super::EventRateLimiter) as a
+//!   [`RegionLimit`], so it gets buckets of its own and the limiter's
+//!   never-suppress list and summaries apply as usual. A limit installed on
+//!   the `Cx` takes precedence; regions without an event quota fall back to
+//!   the limiter's default.
+//! - **Evidence quota**: [`RegionScope::charge_evidence`] admits evidence
+//!   records against a byte budget.
+//!
+//! Every rejection is counted in
+//! `asupersync_telemetry_quota_exceeded_total{region=...,quota=...}`. That
+//! family is exempt from every quota and cap and is reported for every
+//! registered region, so overflow is always visible.
+//!
+//! Quotas are set when a region is registered, typically right after it is
+//! created, and can be changed at runtime with [`RegionScope::set_quota`].
+//! Lowering a quota never drops existing series; it only stops new ones.
+//!
+//! Scrapes can be limited to one region with
+//! [`MetricsRegistry::render_region`] or `/metrics?region=<name>`, and
+//! [`TelemetryUsage`] renders a region's consumption for the console.
+
+use super::entry::LogEntry;
+use super::exposition::{
+    CounterFamily, Family, GaugeFamily, HistogramFamily, MetricOpts, MetricsRegistry,
+    OVERFLOW_LABEL_VALUE, REGION_LABEL, RegistryError, Series,
+};
+use super::rate_limit::{EventRateLimit, RegionLimit};
+use crate::types::RegionId;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Entry field carrying the name of the region an event came from.
+pub const REGION_NAME_FIELD: &str = "region";
+
+/// Name of the always-on family counting quota rejections.
+const QUOTA_EXCEEDED_FAMILY: &str = "asupersync_telemetry_quota_exceeded_total";
+
+/// Entry field set by [`DiagnosticContext`](super::DiagnosticContext).
+pub(super) const REGION_ID_FIELD: &str = "region_id";
+
+/// Telemetry budget for one region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TelemetryQuota {
+    max_series: Option<usize>,
+    event_rate: EventRateLimit,
+    max_evidence_bytes: Option<u64>,
+}
+
+impl Default for TelemetryQuota {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+impl TelemetryQuota {
+    /// No limits.
+    pub const UNLIMITED: Self = Self {
+        max_series: None,
+        event_rate: EventRateLimit::UNLIMITED,
+        max_evidence_bytes: None,
+    };
+
+    /// Caps the distinct label sets held across the region's families.
+    #[must_use]
+    pub const fn with_max_series(mut self, max_series: usize) -> Self {
+        self.max_series = Some(max_series);
+        self
+    }
+
+    /// Rate-limits the region's log events.
+    #[must_use]
+    pub const fn with_event_rate(mut self, limit: EventRateLimit) -> Self {
+        self.event_rate = limit;
+        self
+    }
+
+    /// Caps the evidence bytes the region may hold.
+    #[must_use]
+    pub const fn with_max_evidence_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_evidence_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Returns the series cap, if any.
+    #[must_use]
+    pub const fn max_series(&self) -> Option<usize> {
+        self.max_series
+    }
+
+    /// Returns the event rate limit.
+    #[must_use]
+    pub const fn event_rate(&self) -> EventRateLimit {
+        self.event_rate
+    }
+
+    /// Returns the evidence byte cap, if any.
+    #[must_use]
+    pub const fn max_evidence_bytes(&self) -> Option<u64> {
+        self.max_evidence_bytes
+    }
+}
+
+/// Which quota rejected a piece of telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuotaKind {
+    /// A new label set beyond the series cap.
+    Series,
+    /// An event over the event rate.
+    Events,
+    /// An evidence record beyond the byte cap.
+    EvidenceBytes,
+}
+
+impl QuotaKind {
+    /// Every kind, in reporting order.
+    pub const ALL: [Self; 3] = [Self::Series, Self::Events, Self::EvidenceBytes];
+
+    /// Returns the `quota` label value.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Series => "series",
+            Self::Events => "events",
+            Self::EvidenceBytes => "evidence_bytes",
+        }
+    }
+}
+
+/// Error returned when registering regions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelemetryError {
+    /// Region names must be non-empty and use only ASCII letters, digits,
+    /// `_`, `-`, `.` and `:`, so they can be passed in a scrape URL as is.
+    InvalidName(String),
+    /// Another region is registered under this name.
+    NameTaken(String),
+    /// The region is already registered or attached.
+    AlreadyRegistered(RegionId),
+    /// The region is not registered.
+    UnknownRegion(RegionId),
+}
+
+impl fmt::Display for TelemetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "invalid telemetry region name `{name}`"),
+            Self::NameTaken(name) => write!(f, "telemetry region name `{name}` is taken"),
+            Self::AlreadyRegistered(region) => {
+                write!(f, "region {region} already has a telemetry namespace")
+            }
+            Self::UnknownRegion(region) => {
+                write!(f, "region {region} has no telemetry namespace")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TelemetryError {}
+
+#[derive(Debug)]
+struct Tenant {
+    region: RegionId,
+    name: String,
+    quota: Mutex<TelemetryQuota>,
+    /// (family, non-region label values) of every series the region owns.
+    series: Mutex<BTreeSet<(String, Vec<String>)>>,
+    evidence_bytes: AtomicU64,
+    series_rejected: AtomicU64,
+    events_suppressed: AtomicU64,
+    evidence_rejected: AtomicU64,
+}
+
+impl Tenant {
+    fn exceeded(&self, kind: QuotaKind) -> u64 {
+        let counter = match kind {
+            QuotaKind::Series => &self.series_rejected,
+            QuotaKind::Events => &self.events_suppressed,
+            QuotaKind::EvidenceBytes => &self.evidence_rejected,
+        };
+        counter.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Tenants {
+    /// Keyed by the region's display form, the form log entries carry;
+    /// attached regions share their tenant's entry.
+    by_region: BTreeMap<String, Arc<Tenant>>,
+    by_name: BTreeMap<String, Arc<Tenant>>,
+}
+
+/// Per-region telemetry namespaces over one [`MetricsRegistry`]. Clones
+/// share state.
+#[derive(Debug, Clone)]
+pub struct RegionTelemetry {
+    registry: Arc<MetricsRegistry>,
+    tenants: Arc<RwLock<Tenants>>,
+}
+
+impl RegionTelemetry {
+    /// Creates the namespace table and registers the quota-exceeded family
+    /// on `registry`.
+    ///
+    /// # Errors
+    ///
+    /// The family is already registered, e.g. by another `RegionTelemetry`
+    /// over the same registry.
+    pub fn new(registry: Arc<MetricsRegistry>) -> Result<Self, RegistryError> {
+        let tenants = Arc::new(RwLock::new(Tenants::default()));
+        let report = Arc::clone(&tenants);
+        registry.counter_fn(
+            MetricOpts::new(
+                QUOTA_EXCEEDED_FAMILY,
+                "Telemetry rejected by a region's quota, by region and quota.",
+            )
+            .labels(&[REGION_LABEL, "quota"])
+            .max_series(usize::MAX),
+            move || {
+                let tenants = report.read();
+                tenants
+                    .by_name
+                    .values()
+                    .flat_map(|tenant| {
+                        QuotaKind::ALL.into_iter().map(move |kind| {
+                            let labels = vec![tenant.name.clone(), kind.as_str().to_string()];
+                            (labels, tenant.exceeded(kind))
+                        })
+                    })
+                    .collect()
+            },
+        )?;
+        Ok(Self { registry, tenants })
+    }
+
+    /// Returns the shared registry.
+    #[must_use]
+    pub fn registry(&self) -> &Arc<MetricsRegistry> {
+        &self.registry
+    }
+
+    /// Gives `region` a namespace called `name` with `quota`.
+    ///
+    /// # Errors
+    ///
+    /// The name is invalid or taken, or the region is already registered.
+    pub fn register(
+        &self,
+        region: RegionId,
+        name: impl Into<String>,
+        quota: TelemetryQuota,
+    ) -> Result<RegionScope, TelemetryError> {
+        let name = name.into();
+        if !is_valid_region_name(&name) {
+            return Err(TelemetryError::InvalidName(name));
+        }
+        let mut tenants = self.tenants.write();
+        let key = region.to_string();
+        if tenants.by_region.contains_key(&key) {
+            return Err(TelemetryError::AlreadyRegistered(region));
+        }
+        if tenants.by_name.contains_key(&name) {
+            return Err(TelemetryError::NameTaken(name));
+        }
+        let tenant = Arc::new(Tenant {
+            region,
+            name: name.clone(),
+            quota: Mutex::new(quota),
+            series: Mutex::new(BTreeSet::new()),
+            evidence_bytes: AtomicU64::new(0),
+            series_rejected: AtomicU64::new(0),
+            events_suppressed: AtomicU64::new(0),
+            evidence_rejected: AtomicU64::new(0),
+        });
+        tenants.by_region.insert(key, Arc::clone(&tenant));
+        tenants.by_name.insert(name, Arc::clone(&tenant));
+        drop(tenants);
+        crate::tracing_compat::debug!(
+            region = ?region,
+            quota = ?quota,
+            "telemetry: region namespace registered"
+        );
+        Ok(self.scope_for(tenant))
+    }
+
+    /// Attributes `child`'s events to `tenant`'s namespace and budget, for
+    /// regions created under a registered one.
+    ///
+    /// # Errors
+    ///
+    /// `tenant` is not registered, or `child` already is.
+    pub fn attach(&self, child: RegionId, tenant: RegionId) -> Result<(), TelemetryError> {
+        let mut tenants = self.tenants.write();
+        let owner = tenants
+            .by_region
+            .get(&tenant.to_string())
+            .cloned()
+            .ok_or(TelemetryError::UnknownRegion(tenant))?;
+        let key = child.to_string();
+        if tenants.by_region.contains_key(&key) {
+            return Err(TelemetryError::AlreadyRegistered(child));
+        }
+        tenants.by_region.insert(key, owner);
+        Ok(())
+    }
+
+    /// Removes `region`'s namespace, or detaches it if it was attached.
+    /// Removing a namespace also detaches its attached regions. Series the
+    /// region created stay in the registry. Returns `false` if the region
+    /// was not registered.
+    pub fn remove(&self, region: RegionId) -> bool {
+        let mut tenants = self.tenants.write();
+        let Some(tenant) = tenants.by_region.remove(&region.to_string()) else {
+            return false;
+        };
+        if tenant.region == region {
+            tenants.by_name.remove(&tenant.name);
+            tenants
+                .by_region
+                .retain(|_, other| !Arc::ptr_eq(other, &tenant));
+        }
+        true
+    }
+
+    /// Returns the scope `region` belongs to, if any.
+    #[must_use]
+    pub fn scope(&self, region: RegionId) -> Option<RegionScope> {
+        let tenant = self
+            .tenants
+            .read()
+            .by_region
+            .get(&region.to_string())
+            .cloned()?;
+        Some(self.scope_for(tenant))
+    }
+
+    /// Replaces `region`'s quota. Returns `false` if it is not registered.
+    pub fn set_quota(&self, region: RegionId, quota: TelemetryQuota) -> bool {
+        let Some(scope) = self.scope(region) else {
+            return false;
+        };
+        scope.set_quota(quota);
+        true
+    }
+
+    /// Returns the consumption of every registered namespace, by name.
+    #[must_use]
+    pub fn usage(&self) -> Vec<TelemetryUsage> {
+        let tenants: Vec<_> = self.tenants.read().by_name.values().cloned().collect();
+        tenants
+            .into_iter()
+            .map(|tenant| self.scope_for(tenant).usage())
+            .collect()
+    }
+
+    /// Returns the scope of the region named in `entry`'s context.
+    pub(crate) fn resolve(&self, entry: &LogEntry) -> Option<RegionScope> {
+        let region = entry.get_field(REGION_ID_FIELD)?;
+        let tenant = self.tenants.read().by_region.get(region).cloned()?;
+        Some(self.scope_for(tenant))
+    }
+
+    fn scope_for(&self, tenant: Arc<Tenant>) -> RegionScope {
+        RegionScope {
+            tenant,
+            registry: Arc::clone(&self.registry),
+        }
+    }
+}
+
+/// One region's telemetry namespace.
+#[derive(Debug, Clone)]
+pub struct RegionScope {
+    tenant: Arc<Tenant>,
+    registry: Arc<MetricsRegistry>,
+}
+
+impl RegionScope {
+    /// Returns the region that owns the namespace.
+    #[must_use]
+    pub fn region(&self) -> RegionId {
+        self.tenant.region
+    }
+
+    /// Returns the namespace name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.tenant.name
+    }
+
+    /// Returns the quota in effect.
+    #[must_use]
+    pub fn quota(&self) -> TelemetryQuota {
+        *self.tenant.quota.lock()
+    }
+
+    /// Replaces the quota. Takes effect for the next series lookup, event
+    /// and evidence charge; existing series and held evidence are kept.
+    pub fn set_quota(&self, quota: TelemetryQuota) {
+        *self.tenant.quota.lock() = quota;
+        crate::tracing_compat::debug!(
+            region = ?self.tenant.region,
+            quota = ?quota,
+            "telemetry: region quota changed"
+        );
+    }
+
+    /// Returns the limit the rate limiter should apply to the region's
+    /// events, or `None` to use its default.
+    #[must_use]
+    pub fn event_limit(&self) -> Option<RegionLimit> {
+        let limit = self.quota().event_rate();
+        (!limit.is_unlimited()).then_some(RegionLimit {
+            region: self.tenant.region,
+            limit,
+        })
+    }
+
+    /// Registers (or returns) a counter family in the namespace.
+    ///
+    /// # Errors
+    ///
+    /// As [`MetricsRegistry::counter`]; declaring a `region` label is an
+    /// error.
+    pub fn counter(&self, opts: MetricOpts) -> Result<RegionFamily<CounterFamily>, RegistryError> {
+        let family = self.registry.counter(opts.region_scoped())?;
+        Ok(self.family(family))
+    }
+
+    /// Registers (or returns) a gauge family in the namespace.
+    ///
+    /// # Errors
+    ///
+    /// As [`MetricsRegistry::gauge`]; declaring a `region` label is an
+    /// error.
+    pub fn gauge(&self, opts: MetricOpts) -> Result<RegionFamily<GaugeFamily>, RegistryError> {
+        let family = self.registry.gauge(opts.region_scoped())?;
+        Ok(self.family(family))
+    }
+
+    /// Registers (or returns) a histogram family in the namespace.
+    ///
+    /// # Errors
+    ///
+    /// As [`MetricsRegistry::histogram`]; declaring a `region` label is an
+    /// error.
+    pub fn histogram(
+        &self,
+        opts: MetricOpts,
+        buckets: Vec<f64>,
+    ) -> Result<RegionFamily<HistogramFamily>, RegistryError> {
+        let family = self.registry.histogram(opts.region_scoped(), buckets)?;
+        Ok(self.family(family))
+    }
+
+    /// Charges `bytes` of evidence against the region's budget. Returns
+    /// `false`, and counts the rejection, if they do not fit.
+    pub fn charge_evidence(&self, bytes: u64) -> bool {
+        let max = self.quota().max_evidence_bytes().unwrap_or(u64::MAX);
+        let held = &self.tenant.evidence_bytes;
+        let charged = held
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                current.checked_add(bytes).filter(|total| *total <= max)
+            })
+            .is_ok();
+        if !charged {
+            self.tenant
+                .evidence_rejected
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        charged
+    }
+
+    /// Returns `bytes` of evidence to the region's budget once the records
+    /// are discarded.
+    pub fn release_evidence(&self, bytes: u64) {
+        let _ = self
+            .tenant
+            .evidence_bytes
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                Some(current.saturating_sub(bytes))
+            });
+    }
+
+    /// Returns how many of the region's telemetry items `kind` rejected.
+    #[must_use]
+    pub fn exceeded(&self, kind: QuotaKind) -> u64 {
+        self.tenant.exceeded(kind)
+    }
+
+    /// Returns the region's current consumption.
+    #[must_use]
+    pub fn usage(&self) -> TelemetryUsage {
+        let tenant = &self.tenant;
+        TelemetryUsage {
+            region: tenant.region,
+            name: tenant.name.clone(),
+            quota: self.quota(),
+            series: tenant.series.lock().len(),
+            evidence_bytes: tenant.evidence_bytes.load(Ordering::Acquire),
+            series_rejected: tenant.exceeded(QuotaKind::Series),
+            events_suppressed: tenant.exceeded(QuotaKind::Events),
+            evidence_rejected: tenant.exceeded(QuotaKind::EvidenceBytes),
+        }
+    }
+
+    /// Counts an event the rate limiter suppressed under the region's limit.
+    pub(crate) fn note_event_suppressed(&self) {
+        self.tenant
+            .events_suppressed
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn family<S: Series>(&self, family: Arc<Family<S>>) -> RegionFamily<Family<S>> {
+        RegionFamily {
+            family,
+            tenant: Arc::clone(&self.tenant),
+        }
+    }
+}
+
+/// A metric family seen through one region's namespace: lookups fill in
+/// the region label and count against the region's series quota.
+#[derive(Debug)]
+pub struct RegionFamily<F> {
+    family: Arc<F>,
+    tenant: Arc<Tenant>,
+}
+
+impl<S: Series> RegionFamily<Family<S>> {
+    /// Returns the shared family.
+    #[must_use]
+    pub fn family(&self) -> &Arc<Family<S>> {
+        &self.family
+    }
+
+    /// Returns the region's series for `values` (excluding the region
+    /// label), creating it if the quota allows.
+    ///
+    /// Once the region owns its quota of label sets, new label sets share
+    /// the region's overflow series and count as series rejections.
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::LabelCardinalityMismatch`] if `values` does not
+    /// match the declared label names.
+    pub fn with_labels(&self, values: &[&str]) -> Result<Arc<S>, RegistryError> {
+        let expected = self.family.label_names().len() - 1;
+        if values.len() != expected {
+            return Err(RegistryError::LabelCardinalityMismatch {
+                family: self.family.name().to_string(),
+                expected,
+                actual: values.len(),
+            });
+        }
+        let tenant = &self.tenant;
+        let key: (String, Vec<String>) = (
+            self.family.name().to_string(),
+            values.iter().map(|value| (*value).to_string()).collect(),
+        );
+        let mut owned = tenant.series.lock();
+        let max_series = tenant.quota.lock().max_series().unwrap_or(usize::MAX);
+        let admitted = owned.contains(&key) || (owned.len() < max_series && owned.insert(key));
+        drop(owned);
+        let mut labels = Vec::with_capacity(values.len() + 1);
+        labels.push(tenant.name.as_str());
+        if admitted {
+            labels.extend_from_slice(values);
+        } else {
+            if tenant.series_rejected.fetch_add(1, Ordering::Relaxed) == 0 {
+                crate::tracing_compat::warn!(
+                    "telemetry: region '{}' reached its series quota ({}); \
+                     new label sets are folded into its overflow series",
+                    tenant.name,
+                    max_series
+                );
+            }
+            labels.extend(std::iter::repeat_n(OVERFLOW_LABEL_VALUE, values.len()));
+        }
+        self.family.with_labels(&labels)
+    }
+}
+
+/// A region's telemetry consumption, for the console's region view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetryUsage {
+    /// Region that owns the namespace.
+    pub region: RegionId,
+    /// Namespace name.
+    pub name: String,
+    /// Quota in effect.
+    pub quota: TelemetryQuota,
+    /// Distinct label sets owned.
+    pub series: usize,
+    /// Evidence bytes held.
+    pub evidence_bytes: u64,
+    /// Label sets folded into overflow.
+    pub series_rejected: u64,
+    /// Events suppressed under the region's limit.
+    pub events_suppressed: u64,
+    /// Evidence records rejected.
+    pub evidence_rejected: u64,
+}
+
+impl fmt::Display for TelemetryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn cap(value: Option<impl fmt::Display>) -> String {
+            value.map_or_else(|| "unlimited".to_string(), |value| value.to_string())
+        }
+        let rate = self.quota.event_rate();
+        let rate = (!rate.is_unlimited())
+            .then(|| format!("{}/s burst {}", rate.per_second(), rate.burst()));
+        writeln!(f, "telemetry quota for {} ({})", self.name, self.region)?;
+        writeln!(
+            f,
+            "  series          {} / {}  rejected {}",
+            self.series,
+            cap(self.quota.max_series()),
+            self.series_rejected
+        )?;
+        writeln!(f, "  events          {}  suppressed {}", cap(rate), self.events_suppressed)?;
+        writeln!(
+            f,
+            "  evidence bytes  {} / {}  rejected {}",
+            self.evidence_bytes,
+            cap(self.quota.max_evidence_bytes()),
+            self.evidence_rejected
+        )
+    }
+}
+
+impl crate::console::Render for TelemetryUsage {
+    fn render(
+        &self,
+        out: &mut String,
+        _caps: &crate::console::Capabilities,
+        _mode: crate::console::ColorMode,
+    ) {
+        out.push_str(&self.to_string());
+    }
+}
+
+fn is_valid_region_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != OVERFLOW_LABEL_VALUE
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b':'))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::observability::exposition::{ExpositionFormat, MetricsHandler};
+    use crate::observability::{
+        DiagnosticContext, EventRateLimiter, LogCollector, LogLevel, RateLimitConfig,
+    };
+    use crate::types::Time;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn region(index: u32) -> RegionId {
+        RegionId::new_for_test(index, 0)
+    }
+
+    fn telemetry() -> RegionTelemetry {
+        RegionTelemetry::new(Arc::new(MetricsRegistry::new())).expect("fresh registry")
+    }
+
+    fn event(region: RegionId, message: &str) -> LogEntry {
+        let context = DiagnosticContext::new().with_region_id(region);
+        LogEntry::info(message)
+            .with_context(&context)
+            .with_timestamp(Time::from_millis(5))
+    }
+
+    #[test]
+    fn series_quota_folds_new_label_sets_into_region_overflow() {
+        init_test("series_quota_folds_new_label_sets_into_region_overflow");
+        let telemetry = telemetry();
+        let scope = telemetry
+            .register(region(1), "alpha", TelemetryQuota::UNLIMITED.with_max_series(3))
+            .expect("register");
+        let requests = scope
+            .counter(MetricOpts::new("requests_total", "Requests").labels(&["route"]))
+            .expect("family");
+        for route in ["r0", "r1", "r2", "r3", "r4"] {
+            requests.with_labels(&[route]).expect("series").inc();
+        }
+        // Label sets the region already owns keep their own series.
+        requests.with_labels(&["r0"]).expect("series").inc();
+
+        let usage = scope.usage();
+        crate::assert_with_log!(usage.series == 3, "owned series", 3, usage.series);
+        let rejected = scope.exceeded(QuotaKind::Series);
+        crate::assert_with_log!(rejected == 2, "series rejections", 2, rejected);
+
+        let text = telemetry.registry().render_prometheus();
+        crate::assert_with_log!(
+            text.contains("requests_total{region=\"alpha\",route=\"r0\"} 2\n"),
+            "owned series keeps counting",
+            "r0 = 2",
+            text
+        );
+        crate::assert_with_log!(
+            text.contains("requests_total{region=\"alpha\",route=\"__overflow__\"} 2\n"),
+            "region overflow series",
+            "overflow = 2",
+            text
+        );
+        crate::assert_with_log!(!text.contains("r3"), "folded label set", "absent", text);
+        crate::assert_with_log!(
+            text.contains(
+                "asupersync_telemetry_quota_exceeded_total{region=\"alpha\",quota=\"series\"} 2\n"
+            ),
+            "quota exceeded metric",
+            "series = 2",
+            text
+        );
+        crate::test_complete!("series_quota_folds_new_label_sets_into_region_overflow");
+    }
+
+    #[test]
+    fn label_cardinality_is_isolated_between_regions() {
+        init_test("label_cardinality_is_isolated_between_regions");
+        let telemetry = telemetry();
+        let quota = TelemetryQuota::UNLIMITED.with_max_series(5);
+        let noisy = telemetry.register(region(1), "noisy", quota).expect("noisy");
+        let quiet = telemetry.register(region(2), "quiet", quota).expect("quiet");
+        let opts = || MetricOpts::new("jobs_total", "Jobs").labels(&["id"]);
+        let noisy_jobs = noisy.counter(opts()).expect("noisy family");
+        let quiet_jobs = quiet.counter(opts()).expect("quiet family");
+
+        for id in 0..100 {
+            let id = id.to_string();
+            noisy_jobs.with_labels(&[id.as_str()]).expect("series").inc();
+        }
+        let mut quiet_series = Vec::new();
+        for id in ["a", "b", "c"] {
+            let series = quiet_jobs.with_labels(&[id]).expect("series");
+            series.inc();
+            quiet_series.push(series);
+        }
+
+        let rejected = noisy.exceeded(QuotaKind::Series);
+        crate::assert_with_log!(rejected == 95, "noisy rejections", 95, rejected);
+        let rejected = quiet.exceeded(QuotaKind::Series);
+        crate::assert_with_log!(rejected == 0, "quiet rejections", 0, rejected);
+        let distinct = !Arc::ptr_eq(&quiet_series[0], &quiet_series[1]);
+        crate::assert_with_log!(distinct, "quiet series distinct", true, distinct);
+        let overflow = noisy_jobs.family().overflow_rejections();
+        crate::assert_with_log!(overflow == 0, "shared family cap untouched", 0, overflow);
+
+        let text = telemetry.registry().render_prometheus();
+        for id in ["a", "b", "c"] {
+            let line = format!("jobs_total{{region=\"quiet\",id=\"{id}\"}} 1\n");
+            crate::assert_with_log!(text.contains(&line), "quiet series", line, text);
+        }
+        crate::assert_with_log!(
+            text.contains("jobs_total{region=\"noisy\",id=\"__overflow__\"} 95\n"),
+            "noisy overflow",
+            95,
+            text
+        );
+        crate::assert_with_log!(
+            !text.contains("region=\"quiet\",id=\"__overflow__\""),
+            "quiet has no overflow",
+            "absent",
+            text
+        );
+        crate::test_complete!("label_cardinality_is_isolated_between_regions");
+    }
+
+    #[test]
+    fn event_quota_feeds_the_rate_limiter() {
+        init_test("event_quota_feeds_the_rate_limiter");
+        let telemetry = telemetry();
+        let noisy = telemetry
+            .register(
+                region(1),
+                "noisy",
+                TelemetryQuota::UNLIMITED.with_event_rate(EventRateLimit::new(1, 2)),
+            )
+            .expect("noisy");
+        telemetry
+            .register(region(2), "quiet", TelemetryQuota::UNLIMITED)
+            .expect("quiet");
+        telemetry.attach(region(3), region(1)).expect("attach child");
+        let limiter = EventRateLimiter::new(RateLimitConfig::new(EventRateLimit::new(4, 4)));
+        let collector = LogCollector::new(1_000)
+            .with_min_level(LogLevel::Trace)
+            .with_rate_limiter(limiter.clone())
+            .with_region_telemetry(telemetry.clone());
+
+        for _ in 0..5 {
+            collector.log(event(region(1), "tick"));
+            collector.log(event(region(3), "tick"));
+            collector.log(event(region(2), "tick"));
+        }
+        // The limiter's never-suppress list still wins over a region quota.
+        collector.log(event(region(1), "cancel requested"));
+
+        let entries = collector.drain();
+        let count = |name: &str, message: &str| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    entry.get_field(REGION_NAME_FIELD) == Some(name) && entry.message() == message
+                })
+                .count()
+        };
+        // The child shares the tenant's bucket: 2 of its 10 ticks get through.
+        let noisy_ticks = count("noisy", "tick");
+        crate::assert_with_log!(noisy_ticks == 2, "noisy ticks", 2, noisy_ticks);
+        // Without an event quota the limiter's default (burst 4) applies.
+        let quiet_ticks = count("quiet", "tick");
+        crate::assert_with_log!(quiet_ticks == 4, "quiet ticks", 4, quiet_ticks);
+        let cancels = count("noisy", "cancel requested");
+        crate::assert_with_log!(cancels == 1, "never suppressed", 1, cancels);
+
+        let suppressed = noisy.exceeded(QuotaKind::Events);
+        crate::assert_with_log!(suppressed == 8, "noisy suppressed", 8, suppressed);
+        // Suppression under the limiter's default is not a quota rejection.
+        let quiet = telemetry.scope(region(2)).expect("quiet scope");
+        let suppressed = quiet.exceeded(QuotaKind::Events);
+        crate::assert_with_log!(suppressed == 0, "quiet rejections", 0, suppressed);
+        let total = limiter.suppressed_total();
+        crate::assert_with_log!(total == 9, "limiter total", 9, total);
+        crate::test_complete!("event_quota_feeds_the_rate_limiter");
+    }
+
+    #[test]
+    fn scrapes_filter_to_one_region() {
+        init_test("scrapes_filter_to_one_region");
+        let telemetry = telemetry();
+        let registry = Arc::clone(telemetry.registry());
+        let alpha = telemetry
+            .register(region(1), "alpha", TelemetryQuota::UNLIMITED)
+            .expect("alpha");
+        let beta = telemetry
+            .register(region(2), "beta", TelemetryQuota::UNLIMITED)
+            .expect("beta");
+        for scope in [&alpha, &beta] {
+            let depth = scope
+                .gauge(MetricOpts::new("queue_depth", "Queue depth"))
+                .expect("gauge");
+            depth.with_labels(&[]).expect("series").set(7);
+            let latency = scope
+                .histogram(MetricOpts::new("latency_seconds", "Latency"), vec![0.1, 1.0])
+                .expect("histogram");
+            latency.with_labels(&[]).expect("series").observe(0.5);
+        }
+        let shared = registry
+            .counter(MetricOpts::new("shared_total", "Unscoped"))
+            .expect("shared");
+        shared.with_labels(&[]).expect("series").inc();
+
+        let text = registry.render_region(ExpositionFormat::Prometheus, "alpha");
+        for expected in [
+            "queue_depth{region=\"alpha\"} 7\n",
+            "latency_seconds_count{region=\"alpha\"} 1\n",
+            "asupersync_telemetry_quota_exceeded_total{region=\"alpha\",quota=\"events\"} 0\n",
+        ] {
+            crate::assert_with_log!(text.contains(expected), "alpha series", expected, text);
+        }
+        crate::assert_with_log!(!text.contains("beta"), "no beta series", "absent", text);
+        crate::assert_with_log!(
+            !text.contains("shared_total"),
+            "no unscoped families",
+            "absent",
+            text
+        );
+
+        let text = registry.render_region(ExpositionFormat::OpenMetrics, "gamma");
+        crate::assert_with_log!(text == "# EOF\n", "unknown region", "# EOF", text);
+
+        let handler = MetricsHandler::new(Arc::clone(&registry));
+        let request =
+            crate::web::extract::Request::new("GET", "/metrics").with_query("region=beta");
+        let response = futures_lite::future::block_on(crate::web::handler::Handler::call(
+            &handler,
+            &crate::Cx::for_testing(),
+            request,
+        ));
+        let body = std::str::from_utf8(&response.body).expect("utf-8");
+        crate::assert_with_log!(
+            body.contains("queue_depth{region=\"beta\"} 7\n") && !body.contains("alpha"),
+            "handler filters by query",
+            "beta only",
+            body
+        );
+        crate::test_complete!("scrapes_filter_to_one_region");
+    }
+
+    #[test]
+    fn runtime_quota_changes_keep_existing_series() {
+        init_test("runtime_quota_changes_keep_existing_series");
+        let telemetry = telemetry();
+        let scope = telemetry
+            .register(region(1), "alpha", TelemetryQuota::UNLIMITED.with_max_series(2))
+            .expect("register");
+        let jobs = scope
+            .counter(MetricOpts::new("jobs_total", "Jobs").labels(&["id"]))
+            .expect("family");
+        let a = jobs.with_labels(&["a"]).expect("a");
+        let b = jobs.with_labels(&["b"]).expect("b");
+        let folded = jobs.with_labels(&["c"]).expect("c");
+        crate::assert_with_log!(!Arc::ptr_eq(&folded, &a), "c folded", "overflow", "a");
+
+        let raised = telemetry.set_quota(region(1), TelemetryQuota::UNLIMITED.with_max_series(4));
+        crate::assert_with_log!(raised, "quota raised", true, raised);
+        let c = jobs.with_labels(&["c"]).expect("c");
+        crate::assert_with_log!(!Arc::ptr_eq(&c, &folded), "c owned", "own series", "overflow");
+        jobs.with_labels(&["d"]).expect("d");
+
+        scope.set_quota(TelemetryQuota::UNLIMITED.with_max_series(1));
+        a.inc();
+        let again = jobs.with_labels(&["a"]).expect("a");
+        again.inc();
+        let kept = Arc::ptr_eq(&again, &a) && Arc::ptr_eq(&jobs.with_labels(&["b"]).unwrap(), &b);
+        crate::assert_with_log!(kept, "existing series kept", true, kept);
+        let e = jobs.with_labels(&["e"]).expect("e");
+        crate::assert_with_log!(Arc::ptr_eq(&e, &folded), "e folded", "overflow", "e");
+
+        let usage = scope.usage();
+        crate::assert_with_log!(usage.series == 4, "owned series", 4, usage.series);
+        crate::assert_with_log!(usage.series_rejected == 2, "rejections", 2, usage.series_rejected);
+        let text = telemetry.registry().render_prometheus();
+        crate::assert_with_log!(
+            text.contains("jobs_total{region=\"alpha\",id=\"a\"} 2\n"),
+            "a keeps its count",
+            2,
+            text
+        );
+        let missing = telemetry.set_quota(region(9), TelemetryQuota::UNLIMITED);
+        crate::assert_with_log!(!missing, "unknown region", false, missing);
+        crate::test_complete!("runtime_quota_changes_keep_existing_series");
+    }
+
+    #[test]
+    fn evidence_budget_and_usage_report() {
+        init_test("evidence_budget_and_usage_report");
+        let telemetry = telemetry();
+        let scope = telemetry
+            .register(
+                region(1),
+                "alpha",
+                TelemetryQuota::UNLIMITED.with_max_evidence_bytes(100),
+            )
+            .expect("register");
+        let charged = scope.charge_evidence(60);
+        crate::assert_with_log!(charged, "fits", true, charged);
+        let charged = scope.charge_evidence(50);
+        crate::assert_with_log!(!charged, "over budget", false, charged);
+        scope.release_evidence(30);
+        let charged = scope.charge_evidence(50);
+        crate::assert_with_log!(charged, "fits after release", true, charged);
+        let rejected = scope.exceeded(QuotaKind::EvidenceBytes);
+        crate::assert_with_log!(rejected == 1, "evidence rejections", 1, rejected);
+
+        let usage = telemetry.usage();
+        crate::assert_with_log!(usage.len() == 1, "one namespace", 1, usage.len());
+        let report = usage[0].to_string();
+        crate::assert_with_log!(
+            report.contains("evidence bytes  80 / 100  rejected 1"),
+            "usage report",
+            "80 / 100",
+            report
+        );
+        crate::assert_with_log!(
+            report.contains("series          0 / unlimited"),
+            "unlimited series",
+            "unlimited",
+            report
+        );
+
+        let taken = telemetry.register(region(2), "alpha", TelemetryQuota::UNLIMITED);
+        crate::assert_with_log!(
+            taken.is_err_and(|err| err == TelemetryError::NameTaken("alpha".into())),
+            "duplicate name",
+            "NameTaken",
+            "ok"
+        );
+        let invalid = telemetry.register(region(2), "bad name", TelemetryQuota::UNLIMITED);
+        crate::assert_with_log!(invalid.is_err(), "invalid name", "InvalidName", "ok");
+        let removed = telemetry.remove(region(1));
+        crate::assert_with_log!(removed, "removed", true, removed);
+        let gone = telemetry.scope(region(1)).is_none();
+        crate::assert_with_log!(gone, "scope gone", true, gone);
+        crate::test_complete!("evidence_budget_and_usage_report");
+    }
+}
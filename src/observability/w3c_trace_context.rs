@@ -142,13 +142,34 @@ impl std::error::Error for TraceContextError {}
 
 impl TraceId {
     /// Creates a new random trace ID.
+    ///
+    /// When a `Cx` is current the id is minted through
+    /// [`Cx::next_trace_id`](crate::cx::Cx::next_trace_id), so lab runs
+    /// replay the same ids; otherwise OS entropy is used.
     #[must_use]
     pub fn new_random() -> Self {
+        if let Some(cx) = crate::cx::Cx::current() {
+            return Self::from_cx(&cx);
+        }
         let mut bytes = [0u8; 16];
         getrandom::fill(&mut bytes).expect("failed to generate random trace ID");
         Self(bytes)
     }
 
+    /// Creates a trace ID from the context's id-generation capability.
+    ///
+    /// The bytes are the big-endian FrankenSuite [`TraceId`](crate::types::TraceId),
+    /// so W3C and FrankenSuite views of a trace stay time-ordered together.
+    #[must_use]
+    pub fn from_cx<Caps: crate::cx::cap::HasRandom>(cx: &crate::cx::Cx<Caps>) -> Self {
+        let mut bytes = cx.next_trace_id().to_bytes();
+        if bytes == [0u8; 16] {
+            // All-zero trace ids are invalid on the wire.
+            bytes[15] = 1;
+        }
+        Self(bytes)
+    }
+
     /// Returns trace ID as hex string.
     #[must_use]
     pub fn to_hex(&self) -> String {
@@ -464,13 +485,31 @@ impl FromStr for TraceId {
 
 impl SpanId {
     /// Creates a new random span ID.
+    ///
+    /// Draws from the current `Cx` entropy when one is installed, falling
+    /// back to OS entropy otherwise.
     #[must_use]
     pub fn new_random() -> Self {
+        if let Some(cx) = crate::cx::Cx::current() {
+            return Self::from_cx(&cx);
+        }
         let mut bytes = [0u8; 8];
         getrandom::fill(&mut bytes).expect("failed to generate random span ID");
         Self(bytes)
     }
 
+    /// Creates a span ID from the context's entropy capability.
+    #[must_use]
+    pub fn from_cx<Caps: crate::cx::cap::HasRandom>(cx: &crate::cx::Cx<Caps>) -> Self {
+        let mut bytes = [0u8; 8];
+        cx.random_bytes(&mut bytes);
+        if bytes == [0u8; 8] {
+            // All-zero span ids are invalid on the wire.
+            bytes[7] = 1;
+        }
+        Self(bytes)
+    }
+
     /// Returns span ID as hex string.
     #[must_use]
     pub fn to_hex(&self) -> String {
@@ -0,0 +1,742 @@
+//! Reusable arena for building and executing plan DAGs on hot paths.
+//!
+//! A [`PlanDag`] owns a `Vec` of children per join/race node and a `String`
+//! per leaf, so building one plan per request costs a handful of small
+//! allocations per node. [`PlanArena`] keeps those allocations alive across
+//! plans instead: nodes, edge lists, leaf labels, and the validation scratch
+//! buffers live in a few flat buffers that are cleared (not freed) when the
+//! next plan begins. Nodes refer to their children and labels by
+//! arena-relative spans, and callers refer to nodes by [`PlanId`], exactly as
+//! with [`PlanDag`].
+//!
+//! ```
+//! use asupersync::plan::{ArenaNodeRef, PlanArena};
+//! use std::time::Duration;
+//!
+//! let mut arena = PlanArena::new();
+//! for _request in 0..3 {
+//!     let mut plan = arena.begin();
+//!     let primary = plan.leaf("primary");
+//!     let replica = plan.leaf("replica");
+//!     let bounded = plan.timeout(replica, Duration::from_millis(20));
+//!     let root = plan.race([primary, bounded]);
+//!     plan.set_root(root);
+//!     plan.validate().expect("valid plan");
+//!     let Some(ArenaNodeRef::Race { children }) = plan.node(root) else {
+//!         panic!("root is a race");
+//!     };
+//!     assert_eq!(children, &[primary, bounded]);
+//! }
+//! assert_eq!(arena.stats().resets, 3);
+//! ```
+//!
+//! # Lifetimes
+//!
+//! [`PlanArena::begin`] hands out an [`ArenaPlan`] that mutably borrows the
+//! arena, and every [`ArenaNodeRef`] borrows the plan. The next `begin` (which
+//! resets the arena) therefore cannot run while a plan, a node view, or an
+//! [`ArenaPlan::execute`] future is still alive; the borrow checker rejects
+//! it. Execution moves the caller's leaf futures into its own state and
+//! keeps nothing from the arena once it completes.
+//!
+//! # Shrinking
+//!
+//! Reuse means one unusually large plan would pin its memory forever. Every
+//! [`ShrinkPolicy::window`] resets the arena compares its reserved capacity
+//! with the largest plan seen in that window and, if it holds more than
+//! [`ShrinkPolicy::factor`] times that, shrinks its buffers back to the
+//! window's high-water mark.
+//!
+//! The rewrite engine still operates on [`PlanDag`]; use
+//! [`ArenaPlan::to_dag`] to hand an arena plan to it.
+
+use super::execute::{BoxFut, PlanExecError, PlanValue};
+use super::{PlanDag, PlanError, PlanId, PlanNode};
+use crate::cx::{Cx, cap};
+use std::mem::size_of;
+use std::time::Duration;
+
+/// When a [`PlanArena`] gives reserved memory back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShrinkPolicy {
+    /// Resets per evaluation window. Zero disables shrinking.
+    pub window: u32,
+    /// Reserved bytes may exceed the window's high-water mark by this factor
+    /// before the arena shrinks.
+    pub factor: usize,
+}
+
+impl ShrinkPolicy {
+    /// A policy that never shrinks.
+    #[must_use]
+    pub const fn never() -> Self {
+        Self {
+            window: 0,
+            factor: 0,
+        }
+    }
+}
+
+impl Default for ShrinkPolicy {
+    fn default() -> Self {
+        Self {
+            window: 64,
+            factor: 4,
+        }
+    }
+}
+
+/// Usage counters for a [`PlanArena`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanArenaStats {
+    /// Bytes occupied by the current plan.
+    pub bytes_used: usize,
+    /// Bytes of capacity the arena holds.
+    pub bytes_reserved: usize,
+    /// Largest `bytes_used` of any plan built in this arena.
+    pub high_water_mark: usize,
+    /// Number of times the arena was reset for a new plan.
+    pub resets: u64,
+    /// Number of times the shrink policy released capacity.
+    pub shrinks: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    /// `span` indexes `labels`.
+    Leaf { ordinal: usize },
+    /// `span` indexes `edges` for the remaining kinds.
+    Join,
+    Race,
+    Timeout(Duration),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ArenaNode {
+    kind: Kind,
+    span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Unvisited,
+    Visiting,
+    Done,
+}
+
+/// Buffer lengths of the largest plan in the current shrink window.
+#[derive(Debug, Clone, Copy, Default)]
+struct Peak {
+    nodes: usize,
+    edges: usize,
+    labels: usize,
+}
+
+/// Reusable storage for plan DAGs. See the [module docs](self).
+#[derive(Debug, Default)]
+pub struct PlanArena {
+    nodes: Vec<ArenaNode>,
+    edges: Vec<PlanId>,
+    labels: String,
+    marks: Vec<Mark>,
+    stack: Vec<(PlanId, usize)>,
+    policy: ShrinkPolicy,
+    peak: Peak,
+    window_resets: u32,
+    stats: PlanArenaStats,
+}
+
+impl PlanArena {
+    /// Creates an empty arena with the default [`ShrinkPolicy`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the shrink policy.
+    #[must_use]
+    pub const fn with_shrink_policy(mut self, policy: ShrinkPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Resets the arena and starts building a new plan in it.
+    pub fn begin(&mut self) -> ArenaPlan<'_> {
+        self.reset();
+        ArenaPlan {
+            arena: self,
+            root: None,
+            leaves: 0,
+        }
+    }
+
+    /// Resets the arena and copies `dag` into it, keeping node ids.
+    pub fn load(&mut self, dag: &PlanDag) -> ArenaPlan<'_> {
+        let mut plan = self.begin();
+        for node in &dag.nodes {
+            match node {
+                PlanNode::Leaf { label } => plan.leaf(label),
+                PlanNode::Join { children } => plan.join(children.iter().copied()),
+                PlanNode::Race { children } => plan.race(children.iter().copied()),
+                PlanNode::Timeout { child, duration } => plan.timeout(*child, *duration),
+            };
+        }
+        plan.root = dag.root;
+        plan
+    }
+
+    /// Returns the arena's usage counters.
+    #[must_use]
+    pub fn stats(&self) -> PlanArenaStats {
+        let bytes_used = self.bytes_used();
+        PlanArenaStats {
+            bytes_used,
+            bytes_reserved: self.bytes_reserved(),
+            high_water_mark: self.stats.high_water_mark.max(bytes_used),
+            ..self.stats
+        }
+    }
+
+    fn bytes_used(&self) -> usize {
+        self.nodes.len() * size_of::<ArenaNode>()
+            + self.edges.len() * size_of::<PlanId>()
+            + self.labels.len()
+    }
+
+    fn bytes_reserved(&self) -> usize {
+        self.nodes.capacity() * size_of::<ArenaNode>()
+            + self.edges.capacity() * size_of::<PlanId>()
+            + self.labels.capacity()
+            + self.marks.capacity() * size_of::<Mark>()
+            + self.stack.capacity() * size_of::<(PlanId, usize)>()
+    }
+
+    fn reset(&mut self) {
+        let used = self.bytes_used();
+        self.stats.high_water_mark = self.stats.high_water_mark.max(used);
+        self.stats.resets += 1;
+        crate::runtime::metrics::record_plan_arena_reset(used as u64);
+
+        self.peak.nodes = self.peak.nodes.max(self.nodes.len());
+        self.peak.edges = self.peak.edges.max(self.edges.len());
+        self.peak.labels = self.peak.labels.max(self.labels.len());
+        self.nodes.clear();
+        self.edges.clear();
+        self.labels.clear();
+        self.marks.clear();
+        self.stack.clear();
+
+        if self.policy.window == 0 {
+            return;
+        }
+        self.window_resets += 1;
+        if self.window_resets < self.policy.window {
+            return;
+        }
+        let peak = std::mem::take(&mut self.peak);
+        self.window_resets = 0;
+        let peak_bytes = peak.nodes * size_of::<ArenaNode>()
+            + peak.edges * size_of::<PlanId>()
+            + peak.labels;
+        if self.bytes_reserved() > peak_bytes.saturating_mul(self.policy.factor) {
+            self.nodes.shrink_to(peak.nodes);
+            self.edges.shrink_to(peak.edges);
+            self.labels.shrink_to(peak.labels);
+            self.marks.shrink_to(peak.nodes);
+            self.stack.shrink_to(peak.nodes);
+            self.stats.shrinks += 1;
+            crate::runtime::metrics::record_plan_arena_shrink();
+        }
+    }
+
+    fn push(&mut self, kind: Kind, span: Span) -> PlanId {
+        let id = PlanId::new(self.nodes.len());
+        self.nodes.push(ArenaNode { kind, span });
+        id
+    }
+
+    fn push_edges(&mut self, children: impl IntoIterator<Item = PlanId>) -> Span {
+        let start = self.edges.len();
+        self.edges.extend(children);
+        Span {
+            start,
+            end: self.edges.len(),
+        }
+    }
+
+    fn children(&self, node: ArenaNode) -> &[PlanId] {
+        match node.kind {
+            Kind::Leaf { .. } => &[],
+            Kind::Join | Kind::Race | Kind::Timeout(_) => {
+                &self.edges[node.span.start..node.span.end]
+            }
+        }
+    }
+
+    /// Marks `id` as being visited, mirroring the checks
+    /// [`PlanDag::validate`] makes on entering a node.
+    fn enter(&mut self, id: PlanId) -> Result<(), PlanError> {
+        let Some(node) = self.nodes.get(id.index()).copied() else {
+            return Err(PlanError::MissingNode {
+                parent: id,
+                child: id,
+            });
+        };
+        match self.marks[id.index()] {
+            Mark::Done => return Ok(()),
+            Mark::Visiting => return Err(PlanError::Cycle { at: id }),
+            Mark::Unvisited => {}
+        }
+        if matches!(node.kind, Kind::Join | Kind::Race) && node.span.start == node.span.end {
+            return Err(PlanError::EmptyChildren { parent: id });
+        }
+        self.marks[id.index()] = Mark::Visiting;
+        self.stack.push((id, 0));
+        Ok(())
+    }
+}
+
+/// A node of an [`ArenaPlan`], borrowed from the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaNodeRef<'p> {
+    /// Leaf computation.
+    Leaf {
+        /// Human-readable label.
+        label: &'p str,
+        /// Position among the plan's leaves, in creation order.
+        ordinal: usize,
+    },
+    /// Join of all children.
+    Join {
+        /// Child nodes that must all complete.
+        children: &'p [PlanId],
+    },
+    /// Race of children.
+    Race {
+        /// Child nodes that race for first completion.
+        children: &'p [PlanId],
+    },
+    /// Timeout applied to a child computation.
+    Timeout {
+        /// Child node being timed.
+        child: PlanId,
+        /// Timeout duration.
+        duration: Duration,
+    },
+}
+
+/// A plan being built in a [`PlanArena`].
+///
+/// The builder methods mirror [`PlanDag`]'s, but copy children and labels
+/// into the arena's shared buffers instead of allocating per node.
+#[derive(Debug)]
+pub struct ArenaPlan<'a> {
+    arena: &'a mut PlanArena,
+    root: Option<PlanId>,
+    leaves: usize,
+}
+
+impl ArenaPlan<'_> {
+    /// Adds a leaf node and returns its id.
+    pub fn leaf(&mut self, label: &str) -> PlanId {
+        let labels = &mut self.arena.labels;
+        let start = labels.len();
+        labels.push_str(label);
+        let span = Span {
+            start,
+            end: labels.len(),
+        };
+        let ordinal = self.leaves;
+        self.leaves += 1;
+        self.arena.push(Kind::Leaf { ordinal }, span)
+    }
+
+    /// Adds a join node and returns its id.
+    pub fn join(&mut self, children: impl IntoIterator<Item = PlanId>) -> PlanId {
+        let span = self.arena.push_edges(children);
+        self.arena.push(Kind::Join, span)
+    }
+
+    /// Adds a race node and returns its id.
+    pub fn race(&mut self, children: impl IntoIterator<Item = PlanId>) -> PlanId {
+        let span = self.arena.push_edges(children);
+        self.arena.push(Kind::Race, span)
+    }
+
+    /// Adds a timeout node and returns its id.
+    pub fn timeout(&mut self, child: PlanId, duration: Duration) -> PlanId {
+        let span = self.arena.push_edges([child]);
+        self.arena.push(Kind::Timeout(duration), span)
+    }
+
+    /// Sets the root node for this plan.
+    pub fn set_root(&mut self, root: PlanId) {
+        self.root = Some(root);
+    }
+
+    /// Returns the root node, if set.
+    #[must_use]
+    pub const fn root(&self) -> Option<PlanId> {
+        self.root
+    }
+
+    /// Returns the number of nodes in this plan.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.arena.nodes.len()
+    }
+
+    /// Returns the number of leaves in this plan.
+    #[must_use]
+    pub const fn leaf_count(&self) -> usize {
+        self.leaves
+    }
+
+    /// Returns a node by id.
+    #[must_use]
+    pub fn node(&self, id: PlanId) -> Option<ArenaNodeRef<'_>> {
+        let node = *self.arena.nodes.get(id.index())?;
+        Some(match node.kind {
+            Kind::Leaf { ordinal } => ArenaNodeRef::Leaf {
+                label: &self.arena.labels[node.span.start..node.span.end],
+                ordinal,
+            },
+            Kind::Join => ArenaNodeRef::Join {
+                children: self.arena.children(node),
+            },
+            Kind::Race => ArenaNodeRef::Race {
+                children: self.arena.children(node),
+            },
+            Kind::Timeout(duration) => ArenaNodeRef::Timeout {
+                child: self.arena.edges[node.span.start],
+                duration,
+            },
+        })
+    }
+
+    /// Validates the plan with the same rules, and the same first error, as
+    /// [`PlanDag::validate`], using the arena's scratch buffers.
+    pub fn validate(&mut self) -> Result<(), PlanError> {
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+        let arena = &mut *self.arena;
+        arena.marks.clear();
+        arena.marks.resize(arena.nodes.len(), Mark::Unvisited);
+        arena.stack.clear();
+        arena.enter(root)?;
+        while let Some(&(id, next)) = arena.stack.last() {
+            let node = arena.nodes[id.index()];
+            let Some(&child) = arena.children(node).get(next) else {
+                arena.stack.pop();
+                arena.marks[id.index()] = Mark::Done;
+                continue;
+            };
+            if let Some(top) = arena.stack.last_mut() {
+                top.1 += 1;
+            }
+            if child.index() >= arena.nodes.len() {
+                return Err(PlanError::MissingNode { parent: id, child });
+            }
+            arena.enter(child)?;
+        }
+        Ok(())
+    }
+
+    /// Copies this plan into an owned [`PlanDag`] with the same node ids.
+    #[must_use]
+    pub fn to_dag(&self) -> PlanDag {
+        let mut dag = PlanDag::new();
+        for index in 0..self.node_count() {
+            let node = match self.node(PlanId::new(index)) {
+                Some(ArenaNodeRef::Leaf { label, .. }) => PlanNode::Leaf {
+                    label: label.to_owned(),
+                },
+                Some(ArenaNodeRef::Join { children }) => PlanNode::Join {
+                    children: children.to_vec(),
+                },
+                Some(ArenaNodeRef::Race { children }) => PlanNode::Race {
+                    children: children.to_vec(),
+                },
+                Some(ArenaNodeRef::Timeout { child, duration }) => {
+                    PlanNode::Timeout { child, duration }
+                }
+                None => unreachable!("index is below node_count"),
+            };
+            dag.push_node(node);
+        }
+        dag.root = self.root;
+        dag
+    }
+
+    /// Executes the plan with the real combinator interpreter, binding
+    /// `leaves[i]` to the leaf with ordinal `i`.
+    ///
+    /// The plan must be a tree: a leaf reachable twice would have to run its
+    /// one-shot future twice and is rejected with
+    /// [`PlanExecError::SharedNode`]. The future borrows the plan, so the
+    /// arena cannot be reset until it completes or is dropped.
+    #[allow(clippy::future_not_send)] // inline single-task driver, like `ExecPlan::execute`
+    pub async fn execute<'f, T, Caps>(
+        &self,
+        cx: &Cx<Caps>,
+        leaves: Vec<BoxFut<'f, T>>,
+    ) -> Result<PlanValue<T>, PlanExecError>
+    where
+        Caps: cap::HasTime,
+        T: 'f,
+    {
+        super::execute::execute_arena(self, cx, leaves).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::plan::fixtures::all_fixtures;
+    use futures_lite::future::block_on;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn dag_nodes(dag: &PlanDag) -> Vec<PlanNode> {
+        (0..dag.node_count())
+            .map(|index| dag.node(PlanId::new(index)).expect("node").clone())
+            .collect()
+    }
+
+    fn build_request(plan: &mut ArenaPlan<'_>, fan_out: usize) -> PlanId {
+        let mut branches = Vec::with_capacity(fan_out);
+        for _ in 0..fan_out {
+            let primary = plan.leaf("primary");
+            let replica = plan.leaf("replica");
+            let bounded = plan.timeout(replica, Duration::from_millis(25));
+            branches.push(plan.race([primary, bounded]));
+        }
+        let root = plan.join(branches);
+        plan.set_root(root);
+        root
+    }
+
+    #[test]
+    fn fixtures_round_trip_and_validate_identically() {
+        init_test("fixtures_round_trip_and_validate_identically");
+        let mut arena = PlanArena::new();
+        for fixture in all_fixtures() {
+            let mut plan = arena.load(&fixture.dag);
+            let expected = fixture.dag.validate();
+            let actual = plan.validate();
+            crate::assert_with_log!(actual == expected, fixture.name, expected, actual);
+            let round_trip = plan.to_dag();
+            let same = dag_nodes(&round_trip) == dag_nodes(&fixture.dag)
+                && round_trip.root() == fixture.dag.root();
+            crate::assert_with_log!(same, fixture.name, true, same);
+        }
+        crate::test_complete!("fixtures_round_trip_and_validate_identically");
+    }
+
+    #[test]
+    fn invalid_plans_fail_like_plan_dag() {
+        init_test("invalid_plans_fail_like_plan_dag");
+        let mut cases: Vec<PlanDag> = Vec::new();
+
+        let mut empty_join = PlanDag::new();
+        let leaf = empty_join.leaf("a");
+        let empty = empty_join.join(Vec::new());
+        let root = empty_join.join(vec![leaf, empty]);
+        empty_join.set_root(root);
+        cases.push(empty_join);
+
+        let mut missing = PlanDag::new();
+        let leaf = missing.leaf("a");
+        let root = missing.race(vec![leaf, PlanId::new(9)]);
+        missing.set_root(root);
+        cases.push(missing);
+
+        let mut cycle = PlanDag::new();
+        let leaf = cycle.leaf("a");
+        let join = cycle.join(vec![leaf, PlanId::new(2)]);
+        let timeout = cycle.timeout(join, Duration::from_secs(1));
+        cycle.set_root(timeout);
+        cases.push(cycle);
+
+        let mut arena = PlanArena::new();
+        for dag in &cases {
+            let expected = dag.validate();
+            crate::assert_with_log!(expected.is_err(), "case is invalid", true, expected);
+            let actual = arena.load(dag).validate();
+            crate::assert_with_log!(actual == expected, "same error", expected, actual);
+        }
+        crate::test_complete!("invalid_plans_fail_like_plan_dag");
+    }
+
+    #[test]
+    fn repeated_plans_reuse_capacity() {
+        init_test("repeated_plans_reuse_capacity");
+        let mut arena = PlanArena::new();
+        let mut reserved = Vec::new();
+        for round in 0..10 {
+            let mut plan = arena.begin();
+            let root = build_request(&mut plan, 3);
+            plan.validate().expect("valid");
+            crate::assert_with_log!(plan.node_count() == 13, "nodes", 13, plan.node_count());
+            let labels: Vec<_> = (0..plan.node_count())
+                .filter_map(|index| match plan.node(PlanId::new(index)) {
+                    Some(ArenaNodeRef::Leaf { label, ordinal }) => Some((ordinal, label)),
+                    _ => None,
+                })
+                .collect();
+            let expected: Vec<_> = (0..6)
+                .map(|ordinal| (ordinal, if ordinal % 2 == 0 { "primary" } else { "replica" }))
+                .collect();
+            crate::assert_with_log!(labels == expected, "labels", expected, labels);
+            let root_ok = matches!(
+                plan.node(root),
+                Some(ArenaNodeRef::Join { children }) if children.len() == 3
+            );
+            crate::assert_with_log!(root_ok, "root is the join", true, root_ok);
+            if round > 0 {
+                reserved.push(arena.stats().bytes_reserved);
+            }
+        }
+        let stable = reserved.windows(2).all(|pair| pair[0] == pair[1]);
+        crate::assert_with_log!(stable, "no growth after warm-up", true, reserved);
+        let stats = arena.stats();
+        crate::assert_with_log!(stats.resets == 10, "resets", 10, stats.resets);
+        crate::assert_with_log!(
+            stats.high_water_mark == stats.bytes_used,
+            "same-size plans",
+            stats.bytes_used,
+            stats.high_water_mark
+        );
+        crate::test_complete!("repeated_plans_reuse_capacity");
+    }
+
+    #[test]
+    fn shrink_policy_releases_a_spike() {
+        init_test("shrink_policy_releases_a_spike");
+        let policy = ShrinkPolicy {
+            window: 4,
+            factor: 2,
+        };
+        let mut arena = PlanArena::new().with_shrink_policy(policy);
+        build_request(&mut arena.begin(), 500);
+        let spiked = arena.stats();
+        for _ in 0..3 {
+            build_request(&mut arena.begin(), 1);
+        }
+        // The window that saw the spike keeps its memory.
+        let kept = arena.stats();
+        crate::assert_with_log!(kept.shrinks == 0, "spike window keeps", 0, kept.shrinks);
+        for _ in 0..4 {
+            build_request(&mut arena.begin(), 1);
+        }
+        let shrunk = arena.stats();
+        crate::assert_with_log!(shrunk.shrinks == 1, "shrank once", 1, shrunk.shrinks);
+        crate::assert_with_log!(
+            shrunk.bytes_reserved * 10 < spiked.bytes_reserved,
+            "capacity released",
+            spiked.bytes_reserved,
+            shrunk.bytes_reserved
+        );
+        crate::assert_with_log!(
+            shrunk.high_water_mark == spiked.bytes_used,
+            "high-water mark survives shrinking",
+            spiked.bytes_used,
+            shrunk.high_water_mark
+        );
+
+        let mut never = PlanArena::new().with_shrink_policy(ShrinkPolicy::never());
+        build_request(&mut never.begin(), 500);
+        for _ in 0..20 {
+            build_request(&mut never.begin(), 1);
+        }
+        let stats = never.stats();
+        crate::assert_with_log!(stats.shrinks == 0, "never shrinks", 0, stats.shrinks);
+        crate::test_complete!("shrink_policy_releases_a_spike");
+    }
+
+    #[test]
+    fn execution_matches_captured_plan() {
+        init_test("execution_matches_captured_plan");
+        let cx = Cx::for_testing();
+        let mut arena = PlanArena::new();
+        for round in 0..3u32 {
+            let mut plan = arena.begin();
+            let a = plan.leaf("a");
+            let b = plan.leaf("b");
+            let c = plan.leaf("c");
+            let bounded = plan.timeout(c, Duration::from_secs(5));
+            let race = plan.race([b, bounded]);
+            let root = plan.join([a, race]);
+            plan.set_root(root);
+            plan.validate().expect("valid");
+            let leaves: Vec<BoxFut<'_, u32>> = vec![
+                Box::pin(async move { round }),
+                Box::pin(async move { round + 10 }),
+                Box::pin(async move { round + 20 }),
+            ];
+            let arena_value = block_on(plan.execute(&cx, leaves)).expect("arena execution");
+
+            let captured = crate::plan::execute::capture(|p| {
+                let a = p.leaf(async move { round });
+                let b = p.leaf(async move { round + 10 });
+                let c = p.leaf(async move { round + 20 });
+                let bounded = p.timeout(c, Duration::from_secs(5));
+                let race = p.race([b, bounded]);
+                p.join([a, race])
+            })
+            .expect("valid capture");
+            let captured_value = block_on(captured.execute(&cx)).expect("captured execution");
+            crate::assert_with_log!(
+                arena_value == captured_value,
+                "same value",
+                captured_value,
+                arena_value
+            );
+        }
+
+        let mut plan = arena.begin();
+        let shared = plan.leaf("shared");
+        let root = plan.join([shared, shared]);
+        plan.set_root(root);
+        let leaves: Vec<BoxFut<'_, u32>> = vec![Box::pin(async { 1 })];
+        let result = block_on(plan.execute(&cx, leaves));
+        let rejected = matches!(result, Err(PlanExecError::SharedNode { .. }));
+        crate::assert_with_log!(rejected, "shared leaf rejected", true, result);
+
+        let mut plan = arena.begin();
+        let a = plan.leaf("a");
+        let b = plan.leaf("b");
+        let root = plan.race([a, b]);
+        plan.set_root(root);
+        let leaves: Vec<BoxFut<'_, u32>> = vec![Box::pin(async { 1 })];
+        let result = block_on(plan.execute(&cx, leaves));
+        let mismatch = matches!(
+            result,
+            Err(PlanExecError::LeafCountMismatch {
+                expected: 2,
+                provided: 1
+            })
+        );
+        crate::assert_with_log!(mismatch, "leaf count checked", true, result);
+        crate::test_complete!("execution_matches_captured_plan");
+    }
+}
@@ -63,7 +63,10 @@ use crate::combinator::SelectAll;
 use crate::cx::{Cx, cap};
 use crate::util::{DetHashMap, DetHashSet};
 
-use super::{PlanDag, PlanId, PlanNode, RewriteCertificate, RewritePolicy, RewriteRule};
+use super::{
+    ArenaNodeRef, ArenaPlan, PlanDag, PlanId, PlanNode, RewriteCertificate, RewritePolicy,
+    RewriteRule,
+};
 
 /// Type-erased, single-poll leaf future slot keyed (positionally) by node id.
 ///
@@ -195,6 +198,14 @@ pub enum PlanExecError {
         /// The node with no structural counterpart.
         node: NodeId,
     },
+    /// [`ArenaPlan::execute`](super::ArenaPlan::execute) was given a different
+    /// number of leaf futures than the plan has leaves.
+    LeafCountMismatch {
+        /// Leaves in the plan.
+        expected: usize,
+        /// Leaf futures supplied.
+        provided: usize,
+    },
 }
 
 impl std::fmt::Display for PlanExecError {
@@ -248,6 +259,10 @@ impl std::fmt::Display for PlanExecError {
                 "node {} has no structural PlanDag representation",
                 node.index()
             ),
+            Self::LeafCountMismatch { expected, provided } => write!(
+                f,
+                "plan has {expected} leaves but {provided} leaf futures were supplied"
+            ),
         }
     }
 }
@@ -1162,3 +1177,77 @@ fn owned_from_dag<'a, T>(
         }),
     }
 }
+
+/// Executes an arena-built plan through the same [`eval`] engine as
+/// [`ExecPlan::execute`]. See [`ArenaPlan::execute`].
+#[allow(clippy::future_not_send)] // inline single-task driver; see `ExecPlan::execute`
+pub(super) async fn execute_arena<'f, T, Caps>(
+    plan: &ArenaPlan<'_>,
+    cx: &Cx<Caps>,
+    leaves: Vec<BoxFut<'f, T>>,
+) -> Result<PlanValue<T>, PlanExecError>
+where
+    Caps: cap::HasTime,
+    T: 'f,
+{
+    if leaves.len() != plan.leaf_count() {
+        return Err(PlanExecError::LeafCountMismatch {
+            expected: plan.leaf_count(),
+            provided: leaves.len(),
+        });
+    }
+    let root = plan.root().ok_or(PlanExecError::MissingRoot)?;
+    let mut leaf_store: Vec<Option<BoxFut<'f, T>>> = leaves.into_iter().map(Some).collect();
+    let mut seen = vec![false; plan.node_count()];
+    let owned = owned_from_arena(plan, root, root, &mut leaf_store, &mut seen)?;
+    eval(owned, cx).await
+}
+
+/// Builds the owned execution tree for an arena plan. Unlike
+/// [`owned_from_dag`], the plan has not been checked to be a tree, so any node
+/// reached twice (a shared subtree or a cycle) is rejected.
+fn owned_from_arena<'f, T>(
+    plan: &ArenaPlan<'_>,
+    parent: PlanId,
+    id: PlanId,
+    leaf_store: &mut [Option<BoxFut<'f, T>>],
+    seen: &mut [bool],
+) -> Result<OwnedNode<'f, T>, PlanExecError> {
+    let node = NodeId(id.index());
+    let (Some(visited), Some(arena_node)) = (seen.get_mut(id.index()), plan.node(id)) else {
+        return Err(PlanExecError::MissingChild {
+            parent: NodeId(parent.index()),
+            child: node,
+        });
+    };
+    if std::mem::replace(visited, true) {
+        return Err(PlanExecError::SharedNode { node });
+    }
+    match arena_node {
+        ArenaNodeRef::Leaf { ordinal, .. } => {
+            let fut = leaf_store[ordinal]
+                .take()
+                .ok_or(PlanExecError::SharedNode { node })?;
+            Ok(OwnedNode::Leaf(fut))
+        }
+        ArenaNodeRef::Join { children } | ArenaNodeRef::Race { children } => {
+            if children.is_empty() {
+                return Err(PlanExecError::EmptyChildren { parent: node });
+            }
+            let mut kids = Vec::with_capacity(children.len());
+            for &c in children {
+                kids.push(owned_from_arena(plan, id, c, leaf_store, seen)?);
+            }
+            if matches!(arena_node, ArenaNodeRef::Join { .. }) {
+                Ok(OwnedNode::Join(kids))
+            } else {
+                Ok(OwnedNode::Race(kids))
+            }
+        }
+        ArenaNodeRef::Timeout { child, duration } => Ok(OwnedNode::Timeout {
+            child: Box::new(owned_from_arena(plan, id, child, leaf_store, seen)?),
+            duration,
+            node,
+        }),
+    }
+}
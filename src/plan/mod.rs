@@ -600,6 +600,7 @@ impl EGraph {
 }
 
 pub mod analysis;
+pub mod arena;
 pub mod certificate;
 pub mod execute;
 pub mod extractor;
@@ -611,6 +612,7 @@ pub use analysis::{
     NodeAnalysis, ObligationFlow, ObligationSafety, PlanAnalysis, PlanAnalyzer,
     SideConditionChecker, TraceEquivalenceHint,
 };
+pub use arena::{ArenaNodeRef, ArenaPlan, PlanArena, PlanArenaStats, ShrinkPolicy};
 pub use certificate::{
     CertificateVersion, PlanHash, RewriteCertificate, StepVerifyError, VerifyError,
 };
@@ -192,6 +192,10 @@ pub enum ProcessError {
     /// The requested process configuration is internally inconsistent.
     #[error("invalid process configuration: {0}")]
     InvalidConfiguration(String),
+
+    /// The running task's context withholds process spawning.
+    #[error("{0}")]
+    CapabilityDenied(crate::cx::CapabilityDenied),
 }
 
 impl From<ProcessError> for io::Error {
@@ -847,6 +851,8 @@ impl Command {
     /// Returns an error if:
     /// - The program doesn't exist
     /// - Permission is denied
+    /// - The running task was spawned through a context restricted without
+    ///   [`Capability::Process`](crate::cx::Capability::Process)
     /// - Another I/O error occurs
     ///
     /// # Example
@@ -859,6 +865,8 @@ impl Command {
     /// let status = child.wait()?;
     /// ```
     pub fn spawn(&mut self) -> Result<Child, ProcessError> {
+        crate::cx::attenuation::check_ambient_capability(crate::cx::Capability::Process)
+            .map_err(ProcessError::CapabilityDenied)?;
         self.validate_process_group_configuration()?;
 
         let mut cmd = std_process::Command::new(&self.program);
@@ -2431,6 +2439,29 @@ mod tests {
             .expect("child pid should fit pid_t in test")
     }
 
+    #[test]
+    fn test_spawn_denied_under_restricted_context() {
+        init_test("test_spawn_denied_under_restricted_context");
+
+        let cx = Cx::for_testing()
+            .restrict_capabilities(crate::cx::CapabilitySet::none(), "sandbox")
+            .expect("narrowing succeeds");
+        let _guard = Cx::set_current(Some(cx));
+        let err = Command::new("echo")
+            .arg("hello")
+            .spawn()
+            .expect_err("process spawn withheld");
+        match err {
+            ProcessError::CapabilityDenied(denied) => {
+                assert_eq!(denied.capability(), crate::cx::Capability::Process);
+                assert_eq!(denied.site(), "sandbox");
+            }
+            other => panic!("expected CapabilityDenied, got {other:?}"),
+        }
+
+        crate::test_complete!("test_spawn_denied_under_restricted_context");
+    }
+
     #[test]
     fn test_command_echo() {
         init_test("test_command_echo");
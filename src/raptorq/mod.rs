@@ -12,6 +12,9 @@
 //! RaptorQSender / RaptorQReceiver   ← this module
 //!     │                 │
 //!     ▼                 ▼
+//! EncoderCore       DecoderCore       (sans_io: no I/O, caller-supplied time)
+//!     │                 │
+//!     ▼                 ▼
 //! EncodingPipeline  DecodingPipeline  (src/encoding.rs, src/decoding.rs)
 //!     │                 │
 //!     ▼                 ▼
@@ -20,6 +23,9 @@
 //!     ▼                 ▼
 //! SymbolSink         SymbolStream      (src/transport/)
 //! ```
+//!
+//! Custom transports can skip the sender/receiver drivers and embed the
+//! [`sans_io`] cores directly.
 
 pub mod builder;
 pub mod decision_contract;
@@ -31,6 +37,7 @@ pub mod pipeline;
 pub mod proof;
 pub mod regression;
 pub mod rfc6330;
+pub mod sans_io;
 pub mod systematic;
 
 pub use builder::{RaptorQReceiverBuilder, RaptorQSenderBuilder};
@@ -39,6 +46,7 @@ pub use pipeline::{
     SendProgress,
 };
 pub use proof::{DecodeConfig, DecodeProof, DecodeProofBuilder, FailureReason, ProofOutcome};
+pub use sans_io::{DecoderAction, DecoderCore, DiscardReason, EncodedObject, EncoderCore};
 
 #[cfg(any(test, feature = "test-internals"))]
 pub mod test_log_schema;
@@ -6,18 +6,16 @@
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+use super::sans_io::{DecoderAction, DecoderCore, EncoderCore};
 use crate::config::RaptorQConfig;
 use crate::cx::Cx;
-use crate::decoding::{DecodingConfig, DecodingPipeline, RejectReason, SymbolAcceptResult};
-use crate::encoding::{EncodingPipeline, max_object_size};
 use crate::error::{Error, ErrorKind};
 use crate::observability::Metrics;
 use crate::raptorq::systematic::SystematicParams;
-use crate::security::{AuthMode, AuthenticatedSymbol, AuthenticatedSymbolState, SecurityContext};
+use crate::security::{AuthenticatedSymbol, AuthenticatedSymbolState, SecurityContext};
 use crate::transport::error::StreamError;
 use crate::transport::sink::SymbolSink;
 use crate::transport::stream::SymbolStream;
-use crate::types::resource::{PoolConfig, SymbolPool};
 use crate::types::symbol::{ObjectId, ObjectParams};
 
 /// Outcome of a send operation.
@@ -83,7 +81,7 @@ pub struct ReceiveAuthenticationSummary {
 }
 
 impl ReceiveAuthenticationSummary {
-    fn record(&mut self, state: AuthenticatedSymbolState) {
+    pub(super) fn record(&mut self, state: AuthenticatedSymbolState) {
         match state {
             AuthenticatedSymbolState::Verified => {
                 self.verified = self.verified.saturating_add(1);
@@ -164,52 +162,18 @@ impl<T: SymbolSink + Unpin> RaptorQSender<T> {
         object_id: ObjectId,
         data: &[u8],
     ) -> Result<SendOutcome, Error> {
-        // Keep sender-side validation aligned with the encoder/decoder byte contract:
-        // an object may span up to 256 source blocks because SBN is u8.
-        let max_size = max_object_size(self.config.encoding.max_block_size) as u64;
-        if data.len() as u64 > max_size {
-            return Err(Error::data_too_large(data.len() as u64, max_size));
-        }
-
-        // Encode.
-        let total_repair_symbols = compute_total_repair_count(
-            data.len(),
-            self.config.encoding.max_block_size,
-            self.config.encoding.symbol_size as usize,
-            self.config.encoding.repair_overhead,
-        );
-        // Pool max_size must accommodate all source + repair symbols for this
-        // object. The configured pool_size is a hint for pre-allocation, but
-        // the actual need depends on the data length.
-        let sym_size = self.config.encoding.symbol_size as usize;
-        let source_count = if sym_size == 0 {
-            0
-        } else {
-            data.len().div_ceil(sym_size)
-        };
-        let (pool_initial, pool_max) = sender_pool_bounds(
-            self.config.resources.symbol_pool_size,
-            source_count,
-            total_repair_symbols,
-        );
-        let pool = SymbolPool::new(PoolConfig {
-            symbol_size: self.config.encoding.symbol_size,
-            initial_size: pool_initial,
-            max_size: pool_max,
-            allow_growth: true,
-            growth_increment: 64,
-        });
-        let mut encoder = EncodingPipeline::new(self.config.encoding.clone(), pool);
-        let symbol_iter = encoder.encode(object_id, data);
+        let mut core = EncoderCore::new(&self.config, self.security.clone());
+        let encoded = core.push_object(object_id, data)?;
 
-        // Collect encoded symbols, sign them, and transmit.
+        // The driver does not pace, so any `now` releases the next symbol.
+        let now = cx.now();
         let mut symbols_sent = 0usize;
-        for encoded_result in symbol_iter {
+        while core.pending() > 0 {
             cx.checkpoint()?;
 
-            let encoded_sym = encoded_result.map_err(Error::from)?;
-            let symbol = encoded_sym.into_symbol();
-            let auth_symbol = self.sign(symbol);
+            let Some(auth_symbol) = core.poll_output(now) else {
+                break;
+            };
 
             // Synchronous poll loop for send.
             poll_send_blocking(&mut self.transport, auth_symbol)?;
@@ -222,17 +186,13 @@ impl<T: SymbolSink + Unpin> RaptorQSender<T> {
         if let Some(ref mut m) = self.metrics {
             m.counter("raptorq.symbols_sent")
                 .add(symbols_sent.try_into().unwrap_or(u64::MAX));
-        }
-
-        let stats = encoder.stats();
-        if let Some(ref mut m) = self.metrics {
             m.counter("raptorq.objects_sent").increment();
         }
 
         Ok(SendOutcome {
             object_id,
-            source_symbols: stats.source_symbols,
-            repair_symbols: stats.repair_symbols,
+            source_symbols: encoded.source_symbols,
+            repair_symbols: encoded.repair_symbols,
             symbols_sent,
         })
     }
@@ -270,17 +230,6 @@ impl<T: SymbolSink + Unpin> RaptorQSender<T> {
     pub fn transport_mut(&mut self) -> &mut T {
         &mut self.transport
     }
-
-    #[inline]
-    fn sign(&self, symbol: crate::types::Symbol) -> AuthenticatedSymbol {
-        match &self.security {
-            Some(ctx) => ctx.sign_symbol(&symbol),
-            None => AuthenticatedSymbol::new_verified(
-                symbol,
-                crate::security::AuthenticationTag::zero(),
-            ),
-        }
-    }
 }
 
 /// Receiver pipeline: transport → verify → decode.
@@ -317,143 +266,47 @@ impl<S: SymbolStream + Unpin> RaptorQReceiver<S> {
         cx: &Cx,
         params: &ObjectParams,
     ) -> Result<ReceiveOutcome, Error> {
-        let decoding_config = DecodingConfig {
-            symbol_size: self.config.encoding.symbol_size,
-            max_block_size: self.config.encoding.max_block_size,
-            repair_overhead: self.config.encoding.repair_overhead,
-            // Authenticate target-object symbols at the receiver boundary so
-            // strict mode fails closed before decode and ReceiveOutcome can
-            // report whether consumed symbols were actually verified.
-            verify_auth: false,
-            // Let `set_object_params` size the per-block accept cap to K (via
-            // `configure_auto_buffer_limit`). The fixed default (8192) does NOT
-            // scale with K, so for a valid large-K config (e.g. symbol_size 64,
-            // 1 MiB blocks -> K=16384) it would reject legitimately-received
-            // symbols past 8192 and starve the decoder. Mirrors what the live
-            // QUIC/RQ transports already pass.
-            max_buffered_symbols: 0,
-            ..Default::default()
-        };
-
-        let mut decoder = DecodingPipeline::new(decoding_config);
-
-        decoder.set_object_params(*params).map_err(Error::from)?;
-
-        // br-asupersync-x7ad3b: honor the parsed SecurityConfig on the live
-        // receive path. Resolve the effective authentication context: an
-        // explicit builder-supplied context wins; otherwise derive one from the
-        // configured `auth_key_seed` + `auth_mode` so those knobs actually
-        // authenticate symbols instead of being phantom controls. When
-        // `reject_unauthenticated` is set and a context IS active, every
-        // consumed symbol must verify or we fail CLOSED before it can poison the
-        // decode matrix. (When no auth material is available anywhere the
-        // erasure-only / integrity-vs-manifest behavior is preserved unchanged —
-        // hardening this no-key default is the larger e880xo transport change.)
-        // br-asupersync-x7ad3b: a config-derived context is owned for the whole
-        // call; the per-symbol verify below borrows `self.security` OR this
-        // local only within each iteration (never across the `&mut self.source`
-        // read) to keep the disjoint field borrows clean.
-        let config_security = self.config.security.build_context();
-        let has_auth_material = self.security.is_some() || config_security.is_some();
-        let reject_unauthenticated = self.config.security.reject_unauthenticated
-            && self
-                .security
-                .as_ref()
-                .or(config_security.as_ref())
-                .is_some_and(|ctx| ctx.mode() == AuthMode::Strict);
-
-        let mut symbols_received = 0usize;
-        let mut authenticated = has_auth_material;
-        let mut authentication = ReceiveAuthenticationSummary::default();
+        let mut core = DecoderCore::new(&self.config, params, self.security.clone())?;
 
         // Read symbols until decoding completes.
-        while !decoder.is_complete() {
+        while !core.is_complete() {
             cx.checkpoint()?;
 
-            if let Some(mut auth_symbol) = poll_next_blocking(&mut self.source)? {
-                // Skip symbols for other objects.
-                if auth_symbol.symbol().object_id() != params.object_id {
-                    // ubs:ignore - object_id is not a secret
-                    continue;
-                }
-
-                // Explicit builder-supplied context wins; otherwise fall back to
-                // the context derived from the configured `auth_key_seed` +
-                // `auth_mode` so those knobs actually authenticate symbols
-                // instead of being phantom controls.
-                let symbol_verified =
-                    if let Some(ctx) = self.security.as_ref().or(config_security.as_ref()) {
-                        if let Err(err) = ctx.verify_authenticated_symbol(&mut auth_symbol) {
-                            if let Some(ref mut m) = self.metrics {
-                                m.counter("raptorq.auth_rejected").increment();
-                            }
-                            return Err(Error::new(ErrorKind::CorruptedSymbol)
-                                .with_message(err.to_string()));
-                        }
-                        auth_symbol.is_verified()
-                    } else {
-                        false
-                    };
-                let symbol_authentication_state = auth_symbol.authentication_state();
-
-                // br-asupersync-x7ad3b: reject_unauthenticated enforcement. With
-                // auth material available (explicit or config-derived) the
-                // operator has the means to authenticate, so an unverified
-                // symbol is a hard failure rather than a silently-accepted one.
-                // We fail CLOSED before feeding it into the decode matrix.
-                if reject_unauthenticated && has_auth_material && !symbol_verified {
-                    if let Some(ref mut m) = self.metrics {
-                        m.counter("raptorq.auth_rejected").increment();
-                    }
-                    return Err(Error::new(ErrorKind::CorruptedSymbol).with_message(
-                        "symbol rejected: reject_unauthenticated=true and symbol \
-                         did not authenticate",
-                    ));
-                }
-
-                match decoder.feed(auth_symbol).map_err(Error::from)? {
-                    SymbolAcceptResult::Accepted { .. }
-                    | SymbolAcceptResult::DecodingStarted { .. }
-                    | SymbolAcceptResult::BlockComplete { .. } => {
-                        authenticated &= symbol_verified;
-                        authentication.record(symbol_authentication_state);
-                        symbols_received += 1;
+            let now = cx.now();
+            let mut actions = core.handle_timeout(now);
+            if actions.is_empty() {
+                actions = match poll_next_blocking(&mut self.source)? {
+                    Some(auth_symbol) => core.push_authenticated(auth_symbol, now),
+                    None => core.handle_end_of_stream(),
+                };
+            }
+            for action in actions {
+                match action {
+                    DecoderAction::Accepted { .. } => {
                         if let Some(ref mut m) = self.metrics {
                             m.counter("raptorq.symbols_received").increment();
                         }
                     }
-                    SymbolAcceptResult::Rejected(RejectReason::AuthenticationFailed) => {
+                    DecoderAction::AuthRejected => {
                         if let Some(ref mut m) = self.metrics {
                             m.counter("raptorq.auth_rejected").increment();
                         }
-                        return Err(Error::new(ErrorKind::CorruptedSymbol)
-                            .with_message("symbol authentication failed during receive"));
-                    }
-                    SymbolAcceptResult::Duplicate | SymbolAcceptResult::Rejected(_) => {
-                        // Not used for decoding; keep waiting for usable symbols.
                     }
+                    DecoderAction::Failed(err) => return Err(err),
+                    DecoderAction::Discarded { .. }
+                    | DecoderAction::BlockComplete { .. }
+                    | DecoderAction::Complete => {}
                 }
-            } else {
-                let progress = decoder.progress();
-                return Err(Error::insufficient_symbols(
-                    usize_to_u32_saturating(progress.symbols_received),
-                    usize_to_u32_saturating(progress.symbols_needed_estimate),
-                ));
             }
         }
 
-        let data = decoder.into_data().map_err(Error::from)?;
+        let outcome = core.finish()?;
 
         if let Some(ref mut m) = self.metrics {
             m.counter("raptorq.objects_received").increment();
         }
 
-        Ok(ReceiveOutcome {
-            data,
-            symbols_received,
-            authenticated,
-            authentication,
-        })
+        Ok(outcome)
     }
 
     /// Returns a reference to the config.
@@ -557,7 +410,7 @@ fn compute_repair_count_for_source_symbols(source_count: usize, overhead: f64) -
         .max(padding_excess.saturating_add(1))
 }
 
-fn compute_total_repair_count(
+pub(super) fn compute_total_repair_count(
     data_len: usize,
     max_block_size: usize,
     symbol_size: usize,
@@ -588,7 +441,7 @@ fn compute_total_repair_count(
 /// large pre-allocation bursts, while the upper bound preserves enough headroom
 /// for full source+repair coverage.
 #[inline]
-fn sender_pool_bounds(
+pub(super) fn sender_pool_bounds(
     configured_pool_size: usize,
     source_symbols: usize,
     repair_symbols: usize,
@@ -601,7 +454,7 @@ fn sender_pool_bounds(
 }
 
 #[inline]
-fn usize_to_u32_saturating(value: usize) -> u32 {
+pub(super) fn usize_to_u32_saturating(value: usize) -> u32 {
     u32::try_from(value).unwrap_or(u32::MAX)
 }
 
@@ -703,6 +556,7 @@ mod tests {
         clippy::future_not_send
     )]
     use super::*;
+    use crate::encoding::max_object_size;
     use crate::observability::Metrics;
     use crate::security::{AuthMode, AuthenticationTag, SecurityContext};
     use crate::transport::channel;
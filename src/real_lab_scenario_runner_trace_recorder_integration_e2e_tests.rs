@@ -432,12 +432,14 @@ impl TraceComparison {
                 schedule_hash: 0,
                 steps: 0,
                 trace_fingerprint: 0,
+                resources: Default::default(),
             },
             second_certificate: TraceCertificateSnapshot {
                 event_hash: 0,
                 schedule_hash: 0,
                 steps: 0,
                 trace_fingerprint: 0,
+                resources: Default::default(),
             },
             event_comparison: EventComparisonResult {
                 first_event_count: 0,
@@ -24,7 +24,7 @@ pub use obligation::{
 };
 pub use region::{
     AdmissionError, AdmissionKind, PendingSpawnCounter, PendingSpawnReservation, RegionLimits,
-    RegionRecord,
+    RegionRecord, RegionTaskCounts,
 };
 pub use symbol_obligation_tracker::{
     EpochId, EpochWindow, ObligationGuard, SymbolObligation, SymbolObligationKind,
@@ -3,18 +3,20 @@
 //! A region owns tasks and child regions, forming a tree structure.
 //! When a region closes, it waits for all children to complete.
 
+use crate::cx::dispose::RegionDisposalSlot;
 use crate::record::finalizer::{Finalizer, FinalizerStack};
 use crate::record::task::TaskOutcome;
 use crate::runtime::region_heap::{HeapIndex, RegionHeap};
 use crate::tracing_compat::{Span, debug, info_span};
 use crate::types::rref::{RRef, RRefAccessWitness, RRefError};
 use crate::types::{
-    Budget, CancelReason, CapabilityBudget, CurveBudget, RRefAccess, RegionId, TaskId, Time,
+    Budget, CancelReason, CapabilityBudget, CurveBudget, RRefAccess, RegionId, Severity, TaskId,
+    Time,
 };
 use parking_lot::RwLock;
 use std::cell::Cell;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 
 // Thread-local flag to detect reentrant calls and prevent deadlock
 thread_local! {
@@ -210,6 +212,9 @@ pub struct RegionLimits {
     pub max_children: Option<usize>,
     /// Maximum number of live tasks in the region.
     pub max_tasks: Option<usize>,
+    /// Maximum number of live tasks in the region and every region nested
+    /// beneath it.
+    pub max_live_tasks: Option<usize>,
     /// Maximum number of pending obligations in the region.
     pub max_obligations: Option<usize>,
     /// Maximum live bytes allocated in the region heap.
@@ -223,6 +228,7 @@ impl RegionLimits {
     pub const UNLIMITED: Self = Self {
         max_children: None,
         max_tasks: None,
+        max_live_tasks: None,
         max_obligations: None,
         max_heap_bytes: None,
         curve_budget: None,
@@ -249,6 +255,39 @@ impl RegionLimits {
     }
 }
 
+/// Live-task count of a region's whole subtree.
+///
+/// Each region's count links to its parent's; admitting or retiring a task
+/// adjusts every count up to the root, so subtree limits are checked along
+/// the ancestor chain without walking the region tree.
+#[derive(Debug, Default)]
+pub(crate) struct SubtreeTaskCount {
+    live: AtomicUsize,
+    parent: Option<Arc<SubtreeTaskCount>>,
+}
+
+impl SubtreeTaskCount {
+    fn get(&self) -> usize {
+        self.live.load(Ordering::Acquire)
+    }
+
+    fn increment(&self) {
+        let mut next = Some(self);
+        while let Some(count) = next {
+            count.live.fetch_add(1, Ordering::AcqRel);
+            next = count.parent.as_deref();
+        }
+    }
+
+    fn decrement(&self) {
+        let mut next = Some(self);
+        while let Some(count) = next {
+            count.live.fetch_sub(1, Ordering::AcqRel);
+            next = count.parent.as_deref();
+        }
+    }
+}
+
 /// The kind of admission that was denied.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AdmissionKind {
@@ -317,6 +356,40 @@ impl AtomicRegionState {
     }
 }
 
+/// Tasks a region has admitted and how the retired ones finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RegionTaskCounts {
+    /// Tasks admitted over the region's lifetime, including cleanup tasks.
+    pub spawned: u64,
+    /// Tasks that completed successfully.
+    pub ok: u64,
+    /// Tasks that completed with an error.
+    pub err: u64,
+    /// Tasks that completed cancelled.
+    pub cancelled: u64,
+    /// Tasks that panicked.
+    pub panicked: u64,
+}
+
+impl RegionTaskCounts {
+    /// Returns the number of admitted tasks that have not yet been retired
+    /// with a known outcome.
+    #[must_use]
+    pub const fn live(&self) -> u64 {
+        self.spawned.saturating_sub(self.ok + self.err + self.cancelled + self.panicked)
+    }
+
+    fn record(&mut self, severity: Severity) {
+        let slot = match severity {
+            Severity::Ok => &mut self.ok,
+            Severity::Err => &mut self.err,
+            Severity::Cancelled => &mut self.cancelled,
+            Severity::Panicked => &mut self.panicked,
+        };
+        *slot += 1;
+    }
+}
+
 #[derive(Debug)]
 struct RegionInner {
     budget: Budget,
@@ -331,6 +404,11 @@ struct RegionInner {
     /// Region-owned heap for task allocations.
     /// Reclaimed when the region closes to quiescence.
     heap: RegionHeap,
+    /// Class label reported in region lifecycle events.
+    label: Option<Arc<str>>,
+    task_counts: RegionTaskCounts,
+    /// Set once the drain-started lifecycle event has been published.
+    drain_announced: bool,
 }
 
 /// br-asupersync-dx-core-api-v2-u1z5hn.1.2 — Shared pending-spawn counter.
@@ -457,6 +535,11 @@ pub struct RegionRecord {
     /// `Arc`-shared so producers increment without the state lock; the
     /// close path treats a nonzero count as live children.
     pending_spawns: Arc<PendingSpawnCounter>,
+    /// Deferred-disposal queue slot, shared with every task context of the
+    /// region and drained by the runtime at finalization.
+    disposal: Arc<RegionDisposalSlot>,
+    /// Live tasks of this region's subtree, linked to the parent's count.
+    subtree_tasks: Arc<SubtreeTaskCount>,
     /// Tracing span for region lifecycle (only active with tracing-integration feature).
     #[cfg(feature = "tracing-integration")]
     span: Span,
@@ -555,13 +638,34 @@ impl RegionRecord {
                 limits: RegionLimits::UNLIMITED,
                 pending_obligations: 0,
                 heap: RegionHeap::new(),
+                label: None,
+                task_counts: RegionTaskCounts::default(),
+                drain_announced: false,
             }),
             double_resolve_count: AtomicU64::new(0),
             pending_spawns: Arc::new(PendingSpawnCounter::new()),
+            disposal: Arc::new(RegionDisposalSlot::default()),
+            subtree_tasks: Arc::new(SubtreeTaskCount::default()),
             span,
         }
     }
 
+    /// Links this region's subtree task count into its parent's, so tasks
+    /// admitted here also count against every ancestor.
+    #[must_use]
+    pub(crate) fn with_parent_subtree(mut self, parent: Arc<SubtreeTaskCount>) -> Self {
+        self.subtree_tasks = Arc::new(SubtreeTaskCount {
+            live: AtomicUsize::new(0),
+            parent: Some(parent),
+        });
+        self
+    }
+
+    /// Returns the subtree task count that child regions link to.
+    pub(crate) fn subtree_task_counter(&self) -> Arc<SubtreeTaskCount> {
+        Arc::clone(&self.subtree_tasks)
+    }
+
     /// Returns the logical time when the region was created.
     #[must_use]
     pub const fn created_at(&self) -> Time {
@@ -606,6 +710,20 @@ impl RegionRecord {
         self.inner.read().limits.clone()
     }
 
+    /// Returns the live-task limit without cloning the full limits.
+    #[inline]
+    #[must_use]
+    pub fn max_tasks(&self) -> Option<usize> {
+        self.inner.read().limits.max_tasks
+    }
+
+    /// Returns the subtree live-task limit without cloning the full limits.
+    #[inline]
+    #[must_use]
+    pub fn max_live_tasks(&self) -> Option<usize> {
+        self.inner.read().limits.max_live_tasks
+    }
+
     /// Updates the admission limits for this region.
     pub fn set_limits(&self, limits: RegionLimits) {
         self.inner.write().limits = limits;
@@ -639,6 +757,30 @@ impl RegionRecord {
         });
     }
 
+    /// Returns the class label reported in lifecycle events, if any.
+    #[must_use]
+    pub fn label(&self) -> Option<Arc<str>> {
+        self.inner.read().label.clone()
+    }
+
+    /// Sets the class label reported in lifecycle events.
+    pub fn set_label(&self, label: Option<Arc<str>>) {
+        self.inner.write().label = label;
+    }
+
+    /// Returns the admitted and retired task counts.
+    #[must_use]
+    pub fn task_counts(&self) -> RegionTaskCounts {
+        self.inner.read().task_counts
+    }
+
+    /// Marks the drain-started event as published; returns false if it
+    /// already was.
+    pub(crate) fn mark_drain_announced(&self) -> bool {
+        let mut inner = self.inner.write();
+        !std::mem::replace(&mut inner.drain_announced, true)
+    }
+
     /// Strengthens or sets the cancel reason.
     pub fn strengthen_cancel_reason(&self, reason: CancelReason) {
         let mut inner = self.inner.write();
@@ -676,6 +818,14 @@ impl RegionRecord {
         self.inner.read().tasks.len()
     }
 
+    /// Returns the number of live tasks in this region and every region
+    /// nested beneath it.
+    #[inline]
+    #[must_use]
+    pub fn subtree_task_count(&self) -> usize {
+        self.subtree_tasks.get()
+    }
+
     /// Returns a snapshot of task IDs.
     #[must_use]
     pub fn task_ids(&self) -> Vec<TaskId> {
@@ -728,6 +878,27 @@ impl RegionRecord {
         Arc::clone(&self.pending_spawns)
     }
 
+    /// Returns a shared handle to this region's deferred-disposal slot.
+    #[inline]
+    #[must_use]
+    pub(crate) fn disposal_slot(&self) -> Arc<RegionDisposalSlot> {
+        Arc::clone(&self.disposal)
+    }
+
+    /// Returns this region's deferred-disposal queue, creating it on first
+    /// use.
+    #[must_use]
+    pub(crate) fn disposal_queue(&self) -> crate::cx::DisposalQueue {
+        self.disposal.queue(self.id)
+    }
+
+    /// Takes this region's deferred-disposal queue for draining, closing the
+    /// slot. Returns `None` if no guard ever bound to the region, or if the
+    /// slot was already closed.
+    pub(crate) fn close_disposal_slot(&self) -> Option<crate::cx::DisposalQueue> {
+        self.disposal.close()
+    }
+
     /// Reserves one pending-spawn credit on this region
     /// (increment-before-visibility; see [`PendingSpawnCounter`]).
     #[inline]
@@ -822,6 +993,8 @@ impl RegionRecord {
         }
 
         inner.tasks.push(task);
+        inner.task_counts.spawned += 1;
+        self.subtree_tasks.increment();
         drop(inner);
         Ok(())
     }
@@ -845,7 +1018,36 @@ impl RegionRecord {
     /// Removes a task from this region.
     pub fn remove_task(&self, task: TaskId) {
         let mut inner = self.inner.write();
+        let before = inner.tasks.len();
+        inner.tasks.retain(|&t| t != task);
+        if inner.tasks.len() < before {
+            self.subtree_tasks.decrement();
+        }
+    }
+
+    /// Retires a completed task: merges its close outcome, counts how it
+    /// finished, and removes it, all under one lock.
+    pub(crate) fn retire_task(
+        &self,
+        task: TaskId,
+        close_outcome: Option<TaskOutcome>,
+        severity: Option<Severity>,
+    ) {
+        let mut inner = self.inner.write();
+        if let Some(outcome) = close_outcome {
+            inner.close_outcome = Some(match inner.close_outcome.take() {
+                Some(existing) => existing.join(outcome),
+                None => outcome,
+            });
+        }
+        let before = inner.tasks.len();
         inner.tasks.retain(|&t| t != task);
+        if inner.tasks.len() < before {
+            self.subtree_tasks.decrement();
+            if let Some(severity) = severity {
+                inner.task_counts.record(severity);
+            }
+        }
     }
 
     /// Reserves an obligation slot for this region.
@@ -1513,6 +1715,7 @@ impl RegionRecord {
         for task_id in tasks {
             if !inner.tasks.contains(&task_id) {
                 inner.tasks.push(task_id);
+                self.subtree_tasks.increment();
             }
         }
         inner.cancel_reason = cancel_reason;
@@ -1685,6 +1888,7 @@ mod tests {
         region.set_limits(RegionLimits {
             max_children: Some(1),
             max_tasks: Some(2),
+            max_live_tasks: None,
             max_obligations: Some(1),
             max_heap_bytes: None,
             curve_budget: None,
@@ -2422,6 +2626,7 @@ mod tests {
         let region = RegionRecord::new(test_region_id(), None, Budget::default());
         region.set_limits(RegionLimits {
             max_tasks: Some(2),
+            max_live_tasks: None,
             max_children: Some(2),
             max_obligations: Some(2),
             max_heap_bytes: Some(std::mem::size_of::<u64>()),
@@ -13,10 +13,10 @@ use crate::types::task_context::{
 use crate::types::{
     Budget, CancelPhase, CancelReason, CancelWitness, CxInner, Outcome, RegionId, TaskId, Time,
 };
+use crate::util::loom::sync::atomic::{AtomicU8, Ordering};
 use parking_lot::RwLock;
 use smallvec::SmallVec;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, Ordering};
 use std::task::Waker;
 // br-asupersync-1w9aot: removed `use std::time::Instant`. The
 // `created_instant` field (production; tracing-integration only) is now
@@ -511,6 +511,15 @@ impl TaskRecord {
         self.cx = Some(cx);
     }
 
+    /// Returns the call site that spawned this task, if it was recorded.
+    ///
+    /// Set for tasks spawned through [`Cx::spawn`] and [`Cx::spawn_in`] once
+    /// admission has run their factory; labels are not required.
+    #[must_use]
+    pub fn spawn_site(&self) -> Option<crate::trace::SpawnSiteId> {
+        self.cx_inner.as_ref().and_then(|inner| inner.read().spawn_site)
+    }
+
     /// Records that the task was polled on the given lab step.
     pub fn mark_polled(&mut self, step: u64) {
         self.last_polled_step = step;
@@ -1336,6 +1345,18 @@ impl TaskRecord {
         }
     }
 
+    /// Returns the capability restriction this task inherited from the
+    /// context that spawned it, if that context was attenuated.
+    ///
+    /// For tasks spawned through `Cx::spawn*`, inheritance is applied when
+    /// the task's factory first runs, so this is `None` until the first poll.
+    #[must_use]
+    pub fn capability_restriction(&self) -> Option<Arc<crate::cx::CapabilityRestriction>> {
+        self.cx_inner
+            .as_ref()
+            .and_then(|inner| inner.read().capability_restriction.clone())
+    }
+
     /// Marks this task as a local (`!Send`) task pinned to its owner worker.
     ///
     /// Once set, the scheduler must never steal this task across threads.
@@ -1494,6 +1515,36 @@ impl crate::util::Recyclable for TaskRecord {
     }
 }
 
+impl crate::runtime::object_pool::Poolable for TaskRecord {
+    /// A recycled record must not carry any identity, wake routing, or
+    /// scheduling link from the task that last used it.
+    fn is_pristine(&self) -> bool {
+        let blank = crate::util::ArenaIndex::new(0, 0);
+        self.id == TaskId::from_arena(blank)
+            && self.owner == RegionId::from_arena(blank)
+            && matches!(self.state, TaskState::Created)
+            && self.phase.load() == TaskPhase::Created
+            && self.cx_inner.is_none()
+            && self.cx.is_none()
+            && self.deadline.is_none()
+            && self.polls_remaining == 0
+            && self.total_polls == 0
+            && self.last_polled_step == 0
+            && self.waiters.is_empty()
+            && self.cached_waker.is_none()
+            && self.cached_cancel_waker.is_none()
+            && self.cancel_epoch == 0
+            && !self.is_local
+            && self.pinned_worker.is_none()
+            && self.next_in_queue.is_none()
+            && self.prev_in_queue.is_none()
+            && self.queue_tag == 0
+            && self.heap_index.is_none()
+            && self.sched_priority == 0
+            && self.sched_generation == 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(
@@ -3089,6 +3140,37 @@ mod tests {
         crate::test_complete!("transition_table_is_exhaustive");
     }
 
+    #[test]
+    fn reset_clears_every_poisoned_field() {
+        use crate::runtime::object_pool::Poolable;
+        use crate::util::Recyclable;
+        init_test("reset_clears_every_poisoned_field");
+
+        let mut record = TaskRecord::new(task(), region(), Budget::new().with_poll_quota(9));
+        let inner = Arc::new(RwLock::new(CxInner::new(region(), task(), Budget::INFINITE)));
+        record.set_cx_inner(inner);
+        record.id = TaskId::from_arena(ArenaIndex::new(41, 3));
+        record.owner = RegionId::from_arena(ArenaIndex::new(17, 2));
+        record.deadline = Some(Time::from_nanos(99));
+        record.total_polls = 12;
+        record.last_polled_step = 7;
+        record.waiters.push(task());
+        record.cached_waker = Some((Waker::noop().clone(), 3));
+        record.cached_cancel_waker = Some((Waker::noop().clone(), 4));
+        record.cancel_epoch = 5;
+        record.mark_local();
+        record.pin_to_worker(2);
+        record.set_queue_links(Some(task()), Some(task()), 1);
+        record.heap_index = Some(6);
+        record.sched_priority = 8;
+        record.sched_generation = 11;
+        assert!(!record.is_pristine());
+
+        record.reset();
+        assert!(record.is_pristine(), "reset left stale state: {record:?}");
+        crate::test_complete!("reset_clears_every_poisoned_field");
+    }
+
     // Proptest support for TaskPhase
     #[cfg(feature = "test-internals")]
     mod proptest_support {
@@ -27,17 +27,25 @@
 //! harnesses and later real transports share the same protocol and lifecycle rules.
 
 use crate::channel::oneshot;
+use crate::codec::compression::{
+    CompressionAlgorithm, CompressionConfig, CompressionError, PayloadCompressor,
+};
 use crate::cx::Cx;
 use crate::distributed::membership::{LeaseAction, MembershipLeaseReactor, MembershipView};
 use crate::trace::distributed::{LogicalClockHandle, LogicalTime};
 use crate::types::outcome::Outcome;
 use crate::types::{Budget, CancelReason, ObligationId, RegionId, TaskId, Time};
 use crate::util::det_hash::DetHashMap;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::fmt;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::Poll;
 use std::time::Duration;
 
 // ---------------------------------------------------------------------------
@@ -539,6 +547,9 @@ pub enum RemoteError {
     SerializationError(String),
     /// Transport-level error.
     TransportError(String),
+    /// The context was derived with a capability restriction that withholds
+    /// remote spawn.
+    CapabilityDenied(crate::cx::CapabilityDenied),
 }
 
 impl fmt::Display for RemoteError {
@@ -559,6 +570,7 @@ impl fmt::Display for RemoteError {
             Self::RemotePanic(msg) => write!(f, "remote task panicked: {msg}"),
             Self::SerializationError(msg) => write!(f, "serialization error: {msg}"),
             Self::TransportError(msg) => write!(f, "transport error: {msg}"),
+            Self::CapabilityDenied(denied) => write!(f, "remote spawn refused: {denied}"),
         }
     }
 }
@@ -1004,7 +1016,9 @@ impl RemoteHandle {
 /// # Errors
 ///
 /// Returns [`RemoteError::NoCapability`] if the context does not have
-/// a [`RemoteCap`].
+/// a [`RemoteCap`], or [`RemoteError::CapabilityDenied`] if the context was
+/// derived with [`Cx::restrict_capabilities`] without
+/// [`Capability::RemoteSpawn`](crate::cx::Capability::RemoteSpawn).
 ///
 /// # Example
 ///
@@ -1031,6 +1045,8 @@ pub fn spawn_remote(
 ) -> Result<RemoteHandle, RemoteError> {
     // Check capability
     let cap = cx.remote().ok_or(RemoteError::NoCapability)?;
+    cx.check_capability(crate::cx::Capability::RemoteSpawn)
+        .map_err(RemoteError::CapabilityDenied)?;
 
     let remote_task_id = RemoteTaskId::next();
     let region = cx.region_id();
@@ -1364,6 +1380,308 @@ impl Lease {
     }
 }
 
+// ===========================================================================
+// Cross-node lease timing
+// ===========================================================================
+//
+// Lease expiry is only ever computed as a local clock reading plus a
+// duration. A peer's timestamps are used for two things: bounding link delay
+// through an echoed timestamp (both ends of that measurement are on the local
+// clock) and noticing that the peer's clock has drifted. They are never
+// compared against local time to decide whether a lease is held.
+
+/// Timing metadata carried on a [`LeaseRenewal`].
+///
+/// A requester stamps `sent_at`; the grantor's response echoes it back with
+/// the time the grantor held the request, which gives the requester a round
+/// trip measured entirely on its own clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LeaseTiming {
+    /// Sender's clock when the message was sent.
+    pub sent_at: Time,
+    /// `sent_at` of the last renewal received from the peer, echoed unchanged.
+    pub echo: Option<Time>,
+    /// How long the sender held that renewal before this message, by its own
+    /// clock.
+    pub echo_hold: Duration,
+    /// The sender's one-way delay bound for this link, if it has measured one.
+    pub one_way_delay: Option<Duration>,
+}
+
+/// Clock skew tolerance for cross-node lease timing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockSkewConfig {
+    /// Largest peer clock offset accepted silently. Beyond it a
+    /// [`ClockSkewWarning`] is raised and held leases are shortened by the
+    /// excess: a clock that far off may also be running at the wrong rate.
+    pub max_tolerated_skew: Duration,
+    /// Renewal lead time on top of the round trip.
+    pub min_renewal_margin: Duration,
+}
+
+impl Default for ClockSkewConfig {
+    fn default() -> Self {
+        Self {
+            max_tolerated_skew: Duration::from_secs(1),
+            min_renewal_margin: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Raised when a peer's timestamps imply a clock offset beyond
+/// [`ClockSkewConfig::max_tolerated_skew`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClockSkewWarning {
+    /// The peer whose clock disagrees.
+    pub peer: NodeId,
+    /// Estimated peer clock minus local clock, in nanoseconds.
+    pub skew_nanos: i64,
+    /// The configured tolerance.
+    pub tolerated: Duration,
+    /// Amount the skew exceeds the tolerance; held leases are shortened by it.
+    pub excess: Duration,
+}
+
+impl fmt::Display for ClockSkewWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "clock skew with {}: {}ns exceeds tolerance {:?}",
+            self.peer, self.skew_nanos, self.tolerated
+        )
+    }
+}
+
+/// One node's view of a peer's clock: a link delay bound and an offset
+/// estimate, both refreshed by every [`LeaseRenewal`] received from it.
+#[derive(Clone, Debug)]
+pub struct PeerClock {
+    peer: NodeId,
+    config: ClockSkewConfig,
+    /// The peer's `sent_at` and the local receipt time of its last renewal.
+    last_received: Option<(Time, Time)>,
+    one_way_delay: Option<Duration>,
+    skew_nanos: Option<i64>,
+}
+
+impl PeerClock {
+    /// Creates a clock view with no measurements yet.
+    #[must_use]
+    pub fn new(peer: NodeId, config: ClockSkewConfig) -> Self {
+        Self {
+            peer,
+            config,
+            last_received: None,
+            one_way_delay: None,
+            skew_nanos: None,
+        }
+    }
+
+    /// Returns the peer this view describes.
+    #[must_use]
+    pub fn peer(&self) -> &NodeId {
+        &self.peer
+    }
+
+    /// Returns the skew tolerance.
+    #[must_use]
+    pub fn config(&self) -> ClockSkewConfig {
+        self.config
+    }
+
+    /// Returns the one-way delay bound, zero until one has been measured.
+    #[must_use]
+    pub fn one_way_delay(&self) -> Duration {
+        self.one_way_delay.unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns the estimated peer clock minus local clock, in nanoseconds.
+    #[must_use]
+    pub fn skew_nanos(&self) -> Option<i64> {
+        self.skew_nanos
+    }
+
+    /// Returns how far the estimated skew exceeds the tolerance.
+    #[must_use]
+    pub fn excess_skew(&self) -> Duration {
+        self.skew_nanos.map_or(Duration::ZERO, |skew| {
+            Duration::from_nanos(skew.unsigned_abs()).saturating_sub(self.config.max_tolerated_skew)
+        })
+    }
+
+    /// Stamps a renewal sent to the peer at local time `now`.
+    #[must_use]
+    pub fn stamp(&self, now: Time) -> LeaseTiming {
+        let (echo, echo_hold) = self
+            .last_received
+            .map_or((None, Duration::ZERO), |(peer_sent, received)| {
+                (Some(peer_sent), Duration::from_nanos(now.duration_since(received)))
+            });
+        LeaseTiming {
+            sent_at: now,
+            echo,
+            echo_hold,
+            one_way_delay: self.one_way_delay,
+        }
+    }
+
+    /// Folds in the timing of a renewal received at local time `now`.
+    ///
+    /// The delay bound is the echoed round trip less the peer's hold time. A
+    /// whole round trip bounds either direction even on an asymmetric path;
+    /// the peer's own bound is used instead when it is larger. Returns a
+    /// warning when the implied clock offset exceeds the tolerance.
+    pub fn observe(&mut self, timing: &LeaseTiming, now: Time) -> Option<ClockSkewWarning> {
+        self.last_received = Some((timing.sent_at, now));
+        let peer_bound = timing.one_way_delay.unwrap_or(Duration::ZERO);
+        let sent = i128::from(timing.sent_at.as_nanos());
+        let received = i128::from(now.as_nanos());
+        let skew = if let Some(echo) = timing.echo {
+            let round_trip =
+                Duration::from_nanos(now.duration_since(echo)).saturating_sub(timing.echo_hold);
+            self.one_way_delay = Some(round_trip.max(peer_bound));
+            // NTP offset ((t2 - t1) + (t3 - t4)) / 2, where the peer received
+            // the echoed renewal at t2 = t3 - hold.
+            let requested = i128::from(echo.as_nanos());
+            let peer_received = sent - i128::from(duration_nanos(timing.echo_hold));
+            ((peer_received - requested) + (sent - received)) / 2
+        } else {
+            if timing.one_way_delay.is_some() {
+                self.one_way_delay = Some(self.one_way_delay().max(peer_bound));
+            }
+            sent + i128::from(duration_nanos(self.one_way_delay())) - received
+        };
+        let skew = i64::try_from(skew).unwrap_or(if skew < 0 { i64::MIN } else { i64::MAX });
+        self.skew_nanos = Some(skew);
+
+        let excess = self.excess_skew();
+        if excess.is_zero() {
+            return None;
+        }
+        crate::tracing_compat::warn!(
+            peer = %self.peer,
+            skew_nanos = skew,
+            tolerated_ms = self.config.max_tolerated_skew.as_millis(),
+            "peer clock skew exceeds tolerance; shortening leases"
+        );
+        Some(ClockSkewWarning {
+            peer: self.peer.clone(),
+            skew_nanos: skew,
+            tolerated: self.config.max_tolerated_skew,
+            excess,
+        })
+    }
+}
+
+/// Holder-side view of a lease granted by another node.
+///
+/// Expiry is the local receipt time of the latest grant plus its duration,
+/// less the link delay bound (the grantor started counting up to that long
+/// before the grant arrived) and less any skew excess. Since the grantor's
+/// timestamps never enter the comparison, a clock offset between the two
+/// nodes cannot make the holder believe it still holds the lease after the
+/// grantor has let it expire and granted it elsewhere.
+#[derive(Clone, Debug)]
+pub struct HeldLease {
+    remote_task_id: RemoteTaskId,
+    clock: PeerClock,
+    expires_at: Option<Time>,
+    grants: u32,
+}
+
+impl HeldLease {
+    /// Creates a claim on the lease for `remote_task_id` granted by `grantor`.
+    #[must_use]
+    pub fn new(remote_task_id: RemoteTaskId, grantor: NodeId, config: ClockSkewConfig) -> Self {
+        Self {
+            remote_task_id,
+            clock: PeerClock::new(grantor, config),
+            expires_at: None,
+            grants: 0,
+        }
+    }
+
+    /// Returns the leased task ID.
+    #[must_use]
+    pub fn remote_task_id(&self) -> RemoteTaskId {
+        self.remote_task_id
+    }
+
+    /// Returns the granting node.
+    #[must_use]
+    pub fn grantor(&self) -> &NodeId {
+        self.clock.peer()
+    }
+
+    /// Returns the holder's view of the grantor's clock.
+    #[must_use]
+    pub fn clock(&self) -> &PeerClock {
+        &self.clock
+    }
+
+    /// Returns the local expiry time, `None` before the first grant.
+    #[must_use]
+    pub fn expires_at(&self) -> Option<Time> {
+        self.expires_at
+    }
+
+    /// Returns how many grants and renewals have been applied.
+    #[must_use]
+    pub fn grants(&self) -> u32 {
+        self.grants
+    }
+
+    /// Returns true if the lease is held at local time `now`.
+    #[must_use]
+    pub fn is_held(&self, now: Time) -> bool {
+        self.expires_at.is_some_and(|expires_at| now < expires_at)
+    }
+
+    /// Applies a grant or renewal from the grantor received at local time
+    /// `now`.
+    pub fn on_grant(&mut self, renewal: &LeaseRenewal, now: Time) -> Option<ClockSkewWarning> {
+        let warning = self.clock.observe(&renewal.timing, now);
+        let effective = renewal
+            .new_lease
+            .saturating_sub(self.clock.one_way_delay())
+            .saturating_sub(self.clock.excess_skew());
+        self.expires_at = Some(now + effective);
+        self.grants = self.grants.saturating_add(1);
+        warning
+    }
+
+    /// Returns the renewal lead time: a round trip at the delay bound plus
+    /// [`ClockSkewConfig::min_renewal_margin`].
+    #[must_use]
+    pub fn renewal_margin(&self) -> Duration {
+        self.clock
+            .one_way_delay()
+            .saturating_mul(2)
+            .saturating_add(self.clock.config().min_renewal_margin)
+    }
+
+    /// Returns the local time at which to send the next renewal, `None`
+    /// before the first grant.
+    #[must_use]
+    pub fn renew_at(&self) -> Option<Time> {
+        let margin = duration_nanos(self.renewal_margin());
+        self.expires_at
+            .map(|expires_at| Time::from_nanos(expires_at.as_nanos().saturating_sub(margin)))
+    }
+
+    /// Builds a renewal request from `node` sent at local time `now`.
+    #[must_use]
+    pub fn renewal_request(&self, node: NodeId, lease: Duration, now: Time) -> LeaseRenewal {
+        LeaseRenewal {
+            remote_task_id: self.remote_task_id,
+            new_lease: lease,
+            current_state: RemoteTaskState::Running,
+            node,
+            timing: self.clock.stamp(now),
+        }
+    }
+}
+
 // ===========================================================================
 // Membership-driven lease manager (bead 8y37kz.4.3 — suspicion → obligation
 // revocation)
@@ -1544,7 +1862,10 @@ impl IdempotencyRequestFingerprint {
 /// 3. If duplicate: return cached ack/result
 /// 4. If conflict (same key, different params): reject
 ///
-/// Entries are evicted after their TTL expires.
+/// Entries are evicted after their TTL expires. Expiry is computed and
+/// checked on the storing node's clock only: every `now` passed in must be a
+/// local reading, never a timestamp supplied by the requester, so a requester
+/// with a skewed clock cannot shorten or extend a record's lifetime.
 ///
 /// # Thread Safety
 ///
@@ -1754,6 +2075,178 @@ impl fmt::Display for SagaStepError {
 
 impl std::error::Error for SagaStepError {}
 
+/// Liveness policy for a [`Saga::timed_step`].
+///
+/// Both limits are measured with the runtime clock from the moment the step
+/// starts. A step that exceeds either limit is treated as failed and the saga
+/// compensates, even if the remote side later reports success.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SagaStepPolicy {
+    /// Hard execution timeout for the step.
+    pub timeout: Option<Duration>,
+    /// Maximum gap between heartbeats before the step is considered lost.
+    pub heartbeat_window: Option<Duration>,
+}
+
+impl SagaStepPolicy {
+    /// Creates a policy with no timeout and no heartbeat requirement.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            timeout: None,
+            heartbeat_window: None,
+        }
+    }
+
+    /// Sets the hard execution timeout.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Requires a heartbeat at least once per `window`.
+    #[must_use]
+    pub const fn with_heartbeat_window(mut self, window: Duration) -> Self {
+        self.heartbeat_window = Some(window);
+        self
+    }
+
+    fn hard_deadline(&self, started_at: Time) -> Option<Time> {
+        self.timeout
+            .map(|timeout| started_at.saturating_add_nanos(duration_nanos(timeout)))
+    }
+
+    fn heartbeat_deadline(&self, last_heartbeat: Time) -> Option<Time> {
+        self.heartbeat_window
+            .map(|window| last_heartbeat.saturating_add_nanos(duration_nanos(window)))
+    }
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Heartbeat handle passed to a [`Saga::timed_step`] action.
+///
+/// Remote step implementations call [`beat`](Self::beat) whenever they hear
+/// from the remote side, typically on each [`LeaseRenewal`] for the remote
+/// task. Clones share the same heartbeat state.
+#[derive(Debug, Clone)]
+pub struct SagaHeartbeat {
+    last_beat_nanos: Arc<AtomicU64>,
+}
+
+impl SagaHeartbeat {
+    fn new(started_at: Time) -> Self {
+        Self {
+            last_beat_nanos: Arc::new(AtomicU64::new(started_at.as_nanos())),
+        }
+    }
+
+    /// Records a heartbeat at the current runtime time.
+    pub fn beat(&self) {
+        self.beat_at(crate::time::wall_now());
+    }
+
+    /// Records a heartbeat observed at `now`.
+    pub fn beat_at(&self, now: Time) {
+        self.last_beat_nanos
+            .fetch_max(now.as_nanos(), Ordering::AcqRel);
+    }
+
+    /// Returns the time of the most recent heartbeat (the step start if none).
+    #[must_use]
+    pub fn last_beat(&self) -> Time {
+        Time::from_nanos(self.last_beat_nanos.load(Ordering::Acquire))
+    }
+}
+
+/// Why a timed saga step was abandoned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SagaStepExpiry {
+    /// The hard execution timeout elapsed.
+    TimedOut {
+        /// The configured deadline.
+        deadline: Time,
+    },
+    /// No heartbeat arrived within the heartbeat window.
+    HeartbeatLost {
+        /// Time of the last heartbeat (the step start if none arrived).
+        last_heartbeat: Time,
+        /// The configured heartbeat window.
+        window: Duration,
+    },
+}
+
+impl fmt::Display for SagaStepExpiry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimedOut { deadline } => write!(f, "timed out at {deadline}"),
+            Self::HeartbeatLost {
+                last_heartbeat,
+                window,
+            } => write!(
+                f,
+                "heartbeat lost: none within {window:?} after {last_heartbeat}"
+            ),
+        }
+    }
+}
+
+/// An event recorded in the saga journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SagaJournalEvent {
+    /// The forward action started.
+    Started,
+    /// The forward action succeeded and its compensation was registered.
+    Completed,
+    /// The forward action returned an error.
+    Failed {
+        /// The error message.
+        message: String,
+    },
+    /// The step exceeded its hard execution timeout.
+    TimedOut {
+        /// The configured deadline.
+        deadline: Time,
+        /// Runtime time at which the timeout was detected.
+        detected_at: Time,
+    },
+    /// The step missed its heartbeat window.
+    HeartbeatLost {
+        /// Time of the last heartbeat (the step start if none arrived).
+        last_heartbeat: Time,
+        /// The configured heartbeat window.
+        window: Duration,
+        /// Runtime time at which the loss was detected.
+        detected_at: Time,
+    },
+    /// A result arrived for a step that had already expired; it was not applied.
+    LateResultRejected {
+        /// Rendered outcome of the rejected result.
+        outcome: String,
+        /// Runtime time at which the result arrived.
+        received_at: Time,
+    },
+    /// The step's compensation ran.
+    Compensated {
+        /// Description of what the compensation did.
+        result: String,
+    },
+}
+
+/// A single saga journal record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SagaJournalEntry {
+    /// The step this entry belongs to.
+    pub step: StepIndex,
+    /// Description of the step.
+    pub description: String,
+    /// What happened.
+    pub event: SagaJournalEvent,
+}
+
 /// A record of a compensation that was executed during saga abort.
 #[derive(Debug, Clone)]
 pub struct CompensationResult {
@@ -1815,6 +2308,10 @@ pub struct Saga {
     completed_steps: StepIndex,
     /// Results from compensation execution (if aborted).
     compensation_results: Vec<CompensationResult>,
+    /// Append-only record of step and compensation events.
+    journal: Vec<SagaJournalEntry>,
+    /// Timed steps that were abandoned, for late-result rejection.
+    expired_steps: Vec<(StepIndex, SagaStepExpiry)>,
 }
 
 impl fmt::Debug for Saga {
@@ -1824,6 +2321,7 @@ impl fmt::Debug for Saga {
             .field("completed_steps", &self.completed_steps)
             .field("compensations", &self.compensations.len())
             .field("compensation_results", &self.compensation_results)
+            .field("journal", &self.journal.len())
             .finish()
     }
 }
@@ -1837,6 +2335,8 @@ impl Saga {
             compensations: Vec::new(),
             completed_steps: 0,
             compensation_results: Vec::new(),
+            journal: Vec::new(),
+            expired_steps: Vec::new(),
         }
     }
 
@@ -1858,6 +2358,21 @@ impl Saga {
         &self.compensation_results
     }
 
+    /// Returns the saga journal in the order events occurred.
+    #[must_use]
+    pub fn journal(&self) -> &[SagaJournalEntry] {
+        &self.journal
+    }
+
+    /// Returns why a timed step was abandoned, if it was.
+    #[must_use]
+    pub fn step_expiry(&self, step: StepIndex) -> Option<SagaStepExpiry> {
+        self.expired_steps
+            .iter()
+            .find(|(expired, _)| *expired == step)
+            .map(|(_, expiry)| *expiry)
+    }
+
     /// Executes a forward step and registers its compensation.
     ///
     /// The forward action runs immediately. If it succeeds, the compensation
@@ -1886,9 +2401,174 @@ impl Saga {
         );
 
         let step_idx = self.completed_steps;
+        self.record(step_idx, description, SagaJournalEvent::Started);
+        let result = action();
+        self.finish_step(step_idx, description, result, compensate)
+    }
 
-        match action() {
+    /// Executes an asynchronous forward step under a liveness policy.
+    ///
+    /// The action receives a [`SagaHeartbeat`] it should beat while the
+    /// remote side is making progress. The step fails, and the saga
+    /// compensates, when the hard timeout elapses or when no heartbeat
+    /// arrives within the heartbeat window, whichever comes first. Both
+    /// limits use the runtime clock of `cx`, so they fire at exact virtual
+    /// times in the lab runtime. Expiry is journaled as
+    /// [`SagaJournalEvent::TimedOut`] or [`SagaJournalEvent::HeartbeatLost`];
+    /// the action future is dropped and its result is never applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SagaStepError` if the action fails or the step expires. In
+    /// that case, compensations have already been executed before this
+    /// returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the saga is not in `Running` state.
+    #[allow(clippy::future_not_send)]
+    pub async fn timed_step<T, F, Fut>(
+        &mut self,
+        cx: &Cx,
+        description: &str,
+        policy: SagaStepPolicy,
+        action: F,
+        compensate: impl FnOnce() -> String + Send + 'static,
+    ) -> Result<T, SagaStepError>
+    where
+        F: FnOnce(SagaHeartbeat) -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        assert_eq!(
+            self.state,
+            SagaState::Running,
+            "cannot add steps to a saga that is not Running"
+        );
+
+        let step_idx = self.completed_steps;
+        let started_at = cx.now();
+        self.record(step_idx, description, SagaJournalEvent::Started);
+        let heartbeat = SagaHeartbeat::new(started_at);
+        let mut action = std::pin::pin!(action(heartbeat.clone()));
+        let mut timer: Option<crate::time::Sleep> = None;
+
+        let polled = std::future::poll_fn(|task_cx| {
+            let now = cx.now();
+            let hard_deadline = policy.hard_deadline(started_at);
+            let heartbeat_deadline = policy.heartbeat_deadline(heartbeat.last_beat());
+            // Expiry is checked before polling the action: a result that
+            // becomes ready at or after the deadline is late.
+            if let Some(deadline) = hard_deadline.filter(|deadline| now >= *deadline) {
+                return Poll::Ready(Err((SagaStepExpiry::TimedOut { deadline }, now)));
+            }
+            if let (Some(deadline), Some(window)) = (heartbeat_deadline, policy.heartbeat_window)
+                && now >= deadline
+            {
+                return Poll::Ready(Err((
+                    SagaStepExpiry::HeartbeatLost {
+                        last_heartbeat: heartbeat.last_beat(),
+                        window,
+                    },
+                    now,
+                )));
+            }
+            if let Poll::Ready(result) = action.as_mut().poll(task_cx) {
+                return Poll::Ready(Ok(result));
+            }
+            let next_check = match (hard_deadline, heartbeat_deadline) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (deadline, None) | (None, deadline) => deadline,
+            };
+            if let Some(next_check) = next_check {
+                let sleep = timer.insert(crate::time::Sleep::new(next_check));
+                if Pin::new(sleep).poll(task_cx).is_ready() {
+                    task_cx.waker().wake_by_ref();
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+
+        match polled {
+            Ok(result) => self.finish_step(step_idx, description, result, compensate),
+            Err((expiry, detected_at)) => {
+                let event = match expiry {
+                    SagaStepExpiry::TimedOut { deadline } => SagaJournalEvent::TimedOut {
+                        deadline,
+                        detected_at,
+                    },
+                    SagaStepExpiry::HeartbeatLost {
+                        last_heartbeat,
+                        window,
+                    } => SagaJournalEvent::HeartbeatLost {
+                        last_heartbeat,
+                        window,
+                        detected_at,
+                    },
+                };
+                self.record(step_idx, description, event);
+                self.expired_steps.push((step_idx, expiry));
+                let err = SagaStepError {
+                    step: step_idx,
+                    description: description.to_string(),
+                    message: expiry.to_string(),
+                };
+                self.run_compensations();
+                Err(err)
+            }
+        }
+    }
+
+    /// Rejects a result that arrived after its timed step expired.
+    ///
+    /// Remote steps can deliver a [`ResultDelivery`] after the saga already
+    /// gave up on them. Such results must never be applied: this records a
+    /// [`SagaJournalEvent::LateResultRejected`] entry, logs the rejection, and
+    /// returns `true`. Returns `false` if `step` never expired.
+    pub fn reject_late_result(
+        &mut self,
+        step: StepIndex,
+        outcome: &RemoteOutcome,
+        received_at: Time,
+    ) -> bool {
+        #[cfg_attr(not(feature = "tracing-integration"), allow(unused_variables))]
+        let Some(expiry) = self.step_expiry(step) else {
+            return false;
+        };
+        let description = self
+            .journal
+            .iter()
+            .find(|entry| entry.step == step)
+            .map(|entry| entry.description.clone())
+            .unwrap_or_default();
+        crate::tracing_compat::warn!(
+            step = step,
+            description = %description,
+            expiry = %expiry,
+            outcome = %outcome,
+            "rejected late saga step result"
+        );
+        self.record(
+            step,
+            &description,
+            SagaJournalEvent::LateResultRejected {
+                outcome: outcome.to_string(),
+                received_at,
+            },
+        );
+        true
+    }
+
+    fn finish_step<T>(
+        &mut self,
+        step_idx: StepIndex,
+        description: &str,
+        result: Result<T, String>,
+        compensate: impl FnOnce() -> String + Send + 'static,
+    ) -> Result<T, SagaStepError> {
+        match result {
             Ok(value) => {
+                self.record(step_idx, description, SagaJournalEvent::Completed);
                 self.compensations.push(CompensationEntry {
                     step: step_idx,
                     description: description.to_string(),
@@ -1898,6 +2578,13 @@ impl Saga {
                 Ok(value)
             }
             Err(msg) => {
+                self.record(
+                    step_idx,
+                    description,
+                    SagaJournalEvent::Failed {
+                        message: msg.clone(),
+                    },
+                );
                 let err = SagaStepError {
                     step: step_idx,
                     description: description.to_string(),
@@ -1909,6 +2596,14 @@ impl Saga {
         }
     }
 
+    fn record(&mut self, step: StepIndex, description: &str, event: SagaJournalEvent) {
+        self.journal.push(SagaJournalEntry {
+            step,
+            description: description.to_string(),
+            event,
+        });
+    }
+
     /// Marks the saga as successfully completed.
     ///
     /// After completion, the registered compensations are dropped (they
@@ -1950,6 +2645,13 @@ impl Saga {
         let compensations: Vec<_> = self.compensations.drain(..).collect();
         for entry in compensations.into_iter().rev() {
             let result_desc = (entry.compensate)();
+            self.record(
+                entry.step,
+                &entry.description,
+                SagaJournalEvent::Compensated {
+                    result: result_desc.clone(),
+                },
+            );
             self.compensation_results.push(CompensationResult {
                 step: entry.step,
                 description: entry.description,
@@ -2194,6 +2896,8 @@ pub enum SpawnRejectReason {
     InvalidInput(String),
     /// The idempotency key was already used with different parameters.
     IdempotencyConflict,
+    /// The sender is not authorized to spawn this computation.
+    Unauthorized(String),
 }
 
 impl fmt::Display for SpawnRejectReason {
@@ -2204,6 +2908,7 @@ impl fmt::Display for SpawnRejectReason {
             Self::NodeShuttingDown => write!(f, "node shutting down"),
             Self::InvalidInput(msg) => write!(f, "invalid input: {msg}"),
             Self::IdempotencyConflict => write!(f, "idempotency conflict"),
+            Self::Unauthorized(reason) => write!(f, "unauthorized: {reason}"),
         }
     }
 }
@@ -2312,6 +3017,149 @@ pub struct LeaseRenewal {
     pub current_state: RemoteTaskState,
     /// Node sending the renewal.
     pub node: NodeId,
+    /// Sender clock and link delay metadata; see [`HeldLease`].
+    pub timing: LeaseTiming,
+}
+
+// ---------------------------------------------------------------------------
+// Payload compression
+// ---------------------------------------------------------------------------
+
+/// Per-connection payload compression for remote protocol messages.
+///
+/// Compression applies to the opaque bytes of [`SpawnRequest::input`] and of
+/// successful [`ResultDelivery`] outcomes; everything else travels as-is.
+/// When a connection opens, each side sends its [`offer`](Self::offer) and
+/// records the peer's with [`negotiate`](Self::negotiate). If both offers are
+/// non-empty, payloads on that connection are framed as
+/// [`CompressedPayload`](crate::codec::compression::CompressedPayload)
+/// envelopes, each compressed above the threshold with the first algorithm of
+/// this side's offer that the peer decodes. A peer that offers nothing, such
+/// as one that predates payload compression, keeps receiving the raw bytes it
+/// expects in both directions.
+///
+/// Decisions depend only on payload sizes, the configuration and the
+/// negotiated algorithm, so they replay identically under the lab runtime.
+#[derive(Debug)]
+pub struct RemoteCompression {
+    compressor: PayloadCompressor,
+    links: Mutex<DetHashMap<NodeId, CompressionAlgorithm>>,
+}
+
+impl RemoteCompression {
+    /// Creates per-connection compression state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompressionError::Unavailable`] if the configured algorithm
+    /// is not compiled into this build.
+    pub fn new(config: CompressionConfig) -> Result<Self, CompressionError> {
+        Ok(Self {
+            compressor: PayloadCompressor::new(config)?,
+            links: Mutex::new(DetHashMap::default()),
+        })
+    }
+
+    /// Returns the algorithms this node decodes, in preference order.
+    #[must_use]
+    pub fn offer(&self) -> Vec<CompressionAlgorithm> {
+        self.compressor.config().offer()
+    }
+
+    /// Records `peer`'s offer and returns the algorithm this node will
+    /// compress with on that connection.
+    ///
+    /// Returns `None`, leaving the connection unframed, if either side offers
+    /// nothing. Renegotiating replaces the previous agreement.
+    #[must_use]
+    pub fn negotiate(
+        &self,
+        peer: &NodeId,
+        peer_offer: &[CompressionAlgorithm],
+    ) -> Option<CompressionAlgorithm> {
+        let local = self.offer();
+        let mut links = self.links.lock();
+        if local.is_empty() || peer_offer.is_empty() {
+            links.remove(peer);
+            return None;
+        }
+        let algorithm = if self.compressor.config().algorithm() == CompressionAlgorithm::None {
+            CompressionAlgorithm::None
+        } else {
+            CompressionAlgorithm::negotiate(&local, peer_offer)
+        };
+        links.insert(peer.clone(), algorithm);
+        Some(algorithm)
+    }
+
+    /// Returns the algorithm negotiated with `peer`, or `None` if payloads to
+    /// and from `peer` are unframed.
+    #[must_use]
+    pub fn negotiated(&self, peer: &NodeId) -> Option<CompressionAlgorithm> {
+        self.links.lock().get(peer).copied()
+    }
+
+    /// Drops the agreement with `peer`, e.g. when its connection closes.
+    pub fn forget(&self, peer: &NodeId) {
+        self.links.lock().remove(peer);
+    }
+
+    /// Returns the compressor, whose metrics cover every connection.
+    #[must_use]
+    pub fn compressor(&self) -> &PayloadCompressor {
+        &self.compressor
+    }
+
+    /// Frames the payload of a message bound for `peer`.
+    #[must_use]
+    pub fn encode_outbound(&self, peer: &NodeId, message: RemoteMessage) -> RemoteMessage {
+        let Some(algorithm) = self.negotiated(peer) else {
+            return message;
+        };
+        let Ok(message) = map_message_payload(message, |data| {
+            Ok::<_, Infallible>(self.compressor.compress_with(algorithm, &data).encode())
+        });
+        message
+    }
+
+    /// Unframes the payload of a message received from `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is malformed, exceeds the
+    /// decompression bound or does not inflate to its declared length.
+    pub fn decode_inbound(
+        &self,
+        peer: &NodeId,
+        message: RemoteMessage,
+    ) -> Result<RemoteMessage, CompressionError> {
+        if self.negotiated(peer).is_none() {
+            return Ok(message);
+        }
+        map_message_payload(message, |frame| self.compressor.decode(&frame))
+    }
+}
+
+fn map_message_payload<E>(
+    message: RemoteMessage,
+    mut map: impl FnMut(Vec<u8>) -> Result<Vec<u8>, E>,
+) -> Result<RemoteMessage, E> {
+    Ok(match message {
+        RemoteMessage::SpawnRequest(mut req) => {
+            req.input = RemoteInput::new(map(req.input.into_data())?);
+            RemoteMessage::SpawnRequest(req)
+        }
+        RemoteMessage::ResultDelivery(ResultDelivery {
+            remote_task_id,
+            outcome: RemoteOutcome::Success(data),
+            execution_time,
+        }) => RemoteMessage::ResultDelivery(ResultDelivery {
+            remote_task_id,
+            outcome: RemoteOutcome::Success(map(data)?),
+            execution_time,
+        }),
+        other => other,
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -3263,6 +4111,29 @@ mod tests {
         assert_eq!(result.unwrap_err(), RemoteError::NoCapability);
     }
 
+    #[test]
+    fn spawn_remote_from_restricted_cx_is_denied() {
+        let cx: Cx = Cx::for_testing_with_remote(fast_phase0_cap());
+        let restricted = cx
+            .restrict_capabilities(crate::cx::CapabilitySet::none(), "plugin")
+            .expect("narrowing succeeds");
+
+        let err = spawn_remote(
+            &restricted,
+            NodeId::new("worker-1"),
+            ComputationName::new("encode"),
+            RemoteInput::empty(),
+        )
+        .expect_err("remote spawn withheld");
+        match err {
+            RemoteError::CapabilityDenied(denied) => {
+                assert_eq!(denied.capability(), crate::cx::Capability::RemoteSpawn);
+                assert_eq!(denied.site(), "plugin");
+            }
+            other => unreachable!("expected CapabilityDenied, got {other:?}"),
+        }
+    }
+
     #[test]
     fn spawn_remote_with_cap_succeeds() {
         let cx: Cx = Cx::for_testing_with_remote(fast_phase0_cap());
@@ -4308,6 +5179,7 @@ mod tests {
             new_lease: Duration::from_secs(15),
             current_state: RemoteTaskState::Running,
             node: NodeId::new("worker-1"),
+            timing: LeaseTiming::default(),
         };
         let origin = origin
             .recv_lease_renewal(&renewal)
@@ -4416,6 +5288,7 @@ mod tests {
             new_lease: Duration::from_secs(10),
             current_state: RemoteTaskState::Running,
             node: NodeId::new("worker-1"),
+            timing: LeaseTiming::default(),
         };
         let origin = origin
             .recv_lease_renewal(&renewal)
@@ -4726,6 +5599,7 @@ mod tests {
             new_lease: Duration::from_secs(30),
             current_state: RemoteTaskState::Running,
             node: NodeId::new("worker-1"),
+            timing: LeaseTiming::default(),
         };
         assert_eq!(renewal.new_lease, Duration::from_secs(30));
         assert_eq!(renewal.current_state, RemoteTaskState::Running);
@@ -4775,6 +5649,7 @@ mod tests {
             new_lease: Duration::from_secs(30),
             current_state: RemoteTaskState::Running,
             node: NodeId::new("n2"),
+            timing: LeaseTiming::default(),
         });
         assert_eq!(renewal_msg.remote_task_id(), rtid);
     }
@@ -4831,6 +5706,7 @@ mod tests {
             new_lease: Duration::from_secs(10),
             current_state: RemoteTaskState::Running,
             node: NodeId::new("worker-1"),
+            timing: LeaseTiming::default(),
         }
     }
 
@@ -5240,6 +6116,117 @@ mod tests {
         assert!(format!("{}", LeaseError::CreationFailed("full".into())).contains("full"));
     }
 
+    // -----------------------------------------------------------------------
+    // Cross-node lease timing tests
+    // -----------------------------------------------------------------------
+
+    fn skew_config(max_tolerated_skew: Duration) -> ClockSkewConfig {
+        ClockSkewConfig {
+            max_tolerated_skew,
+            ..ClockSkewConfig::default()
+        }
+    }
+
+    fn grant(timing: LeaseTiming, lease: Duration) -> LeaseRenewal {
+        LeaseRenewal {
+            remote_task_id: RemoteTaskId::from_raw(1),
+            new_lease: lease,
+            current_state: RemoteTaskState::Running,
+            node: NodeId::new("grantor"),
+            timing,
+        }
+    }
+
+    #[test]
+    fn held_lease_expiry_uses_local_clock_and_round_trip() {
+        // The grantor's clock runs 5s ahead; 20ms each way.
+        let config = skew_config(Duration::from_secs(10));
+        let mut held = HeldLease::new(RemoteTaskId::from_raw(1), NodeId::new("grantor"), config);
+        let lease = Duration::from_secs(2);
+        let request = held.renewal_request(NodeId::new("holder"), lease, Time::from_secs(100));
+        assert_eq!(request.timing.echo, None);
+
+        let mut grantor_view = PeerClock::new(NodeId::new("holder"), config);
+        assert!(
+            grantor_view
+                .observe(&request.timing, Time::from_millis(105_020))
+                .is_none()
+        );
+        let response = grant(grantor_view.stamp(Time::from_millis(105_020)), lease);
+        assert_eq!(response.timing.echo, Some(Time::from_secs(100)));
+        assert!(held.on_grant(&response, Time::from_millis(100_040)).is_none());
+
+        assert_eq!(held.clock().one_way_delay(), Duration::from_millis(40));
+        assert_eq!(held.clock().skew_nanos(), Some(5_000_000_000));
+        // Two seconds from receipt, less the round-trip delay bound. The
+        // grantor's own expiry is 102.020 on the holder's clock.
+        assert_eq!(held.expires_at(), Some(Time::from_millis(102_000)));
+        assert!(held.is_held(Time::from_millis(101_999)));
+        assert!(!held.is_held(Time::from_millis(102_000)));
+        assert_eq!(held.grants(), 1);
+    }
+
+    #[test]
+    fn renewal_is_scheduled_a_round_trip_plus_margin_before_expiry() {
+        let config = ClockSkewConfig::default();
+        let mut held = HeldLease::new(RemoteTaskId::from_raw(1), NodeId::new("grantor"), config);
+        let lease = Duration::from_secs(5);
+        let mut grantor_view = PeerClock::new(NodeId::new("holder"), config);
+
+        // 50ms each way; the grantor holds the request for 10ms.
+        let request = held.renewal_request(NodeId::new("holder"), lease, Time::from_secs(10));
+        let _ = grantor_view.observe(&request.timing, Time::from_millis(10_050));
+        let response = grant(grantor_view.stamp(Time::from_millis(10_060)), lease);
+        assert_eq!(response.timing.echo_hold, Duration::from_millis(10));
+        assert!(held.on_grant(&response, Time::from_millis(10_110)).is_none());
+        assert_eq!(held.clock().skew_nanos(), Some(0));
+        assert_eq!(held.clock().one_way_delay(), Duration::from_millis(100));
+        assert_eq!(held.expires_at(), Some(Time::from_millis(15_010)));
+        assert_eq!(held.renewal_margin(), Duration::from_millis(300));
+        assert_eq!(held.renew_at(), Some(Time::from_millis(14_710)));
+
+        // The renewal echoes the grant, and the grantor's next response
+        // carries the delay bound it measured from that echo.
+        let renewal = held.renewal_request(NodeId::new("holder"), lease, Time::from_millis(14_710));
+        assert_eq!(renewal.timing.echo, Some(Time::from_millis(10_060)));
+        assert_eq!(renewal.timing.echo_hold, Duration::from_millis(4_600));
+        assert_eq!(renewal.timing.one_way_delay, Some(Duration::from_millis(100)));
+        let _ = grantor_view.observe(&renewal.timing, Time::from_millis(14_760));
+        assert_eq!(grantor_view.one_way_delay(), Duration::from_millis(100));
+        let response = grant(grantor_view.stamp(Time::from_millis(14_760)), lease);
+        assert_eq!(response.timing.one_way_delay, Some(Duration::from_millis(100)));
+        assert!(held.on_grant(&response, Time::from_millis(14_810)).is_none());
+        assert_eq!(held.expires_at(), Some(Time::from_millis(19_710)));
+        assert_eq!(held.grants(), 2);
+    }
+
+    #[test]
+    fn skew_beyond_tolerance_warns_and_shortens_lease() {
+        // The grantor's clock runs 4s ahead against a 1s tolerance.
+        let config = skew_config(Duration::from_secs(1));
+        let mut held = HeldLease::new(RemoteTaskId::from_raw(1), NodeId::new("grantor"), config);
+        let lease = Duration::from_secs(5);
+        let mut grantor_view = PeerClock::new(NodeId::new("holder"), config);
+
+        let request = held.renewal_request(NodeId::new("holder"), lease, Time::from_secs(10));
+        let grantor_warning = grantor_view
+            .observe(&request.timing, Time::from_millis(14_010))
+            .expect("grantor sees the holder behind");
+        assert_eq!(grantor_warning.skew_nanos, -4_010_000_000);
+
+        let response = grant(grantor_view.stamp(Time::from_millis(14_010)), lease);
+        let warning = held
+            .on_grant(&response, Time::from_millis(10_020))
+            .expect("holder sees the grantor ahead");
+        assert_eq!(warning.peer, NodeId::new("grantor"));
+        assert_eq!(warning.skew_nanos, 4_000_000_000);
+        assert_eq!(warning.tolerated, Duration::from_secs(1));
+        assert_eq!(warning.excess, Duration::from_secs(3));
+        // Five seconds, less the 20ms round trip and the 3s excess.
+        assert_eq!(held.expires_at(), Some(Time::from_millis(12_000)));
+        assert!(warning.to_string().contains("exceeds tolerance"));
+    }
+
     // -----------------------------------------------------------------------
     // Idempotency store tests (tmh.2.2)
     // -----------------------------------------------------------------------
@@ -5654,6 +6641,172 @@ mod tests {
         assert_eq!(saga.state(), SagaState::Running);
     }
 
+    /// Runs a two-step saga in the lab: a plain "reserve" step followed by a
+    /// timed "remote charge" step driven by `action`.
+    fn run_timed_saga_in_lab<F, Fut>(
+        policy: SagaStepPolicy,
+        action: F,
+    ) -> (Saga, Result<&'static str, SagaStepError>, Time)
+    where
+        F: FnOnce(SagaHeartbeat) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<&'static str, String>> + Send + 'static,
+    {
+        let mut lab =
+            crate::lab::LabRuntime::new(crate::lab::LabConfig::new(11).with_auto_advance());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let out = Arc::new(Mutex::new(None));
+        let out_task = Arc::clone(&out);
+        let (task, _handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("task Cx");
+                let mut saga = Saga::new();
+                saga.step("reserve", || Ok(()), || "released reservation".to_string())
+                    .expect("reserve succeeds");
+                let result = saga
+                    .timed_step(&cx, "remote charge", policy, action, || {
+                        "refunded charge".to_string()
+                    })
+                    .await;
+                *out_task.lock() = Some((saga, result));
+            })
+            .expect("create saga task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_with_auto_advance();
+        let (saga, result) = out.lock().take().expect("saga task finished");
+        (saga, result, lab.now())
+    }
+
+    #[test]
+    fn saga_timed_step_hard_timeout_fires_at_exact_virtual_time() {
+        let (saga, result, now) = run_timed_saga_in_lab(
+            SagaStepPolicy::new().with_timeout(Duration::from_secs(1)),
+            |_heartbeat| async {
+                crate::time::sleep(crate::time::wall_now(), Duration::from_secs(2)).await;
+                Ok("charged")
+            },
+        );
+
+        let err = result.expect_err("step times out");
+        assert_eq!(err.step, 1);
+        assert_eq!(now, Time::from_secs(1));
+        assert_eq!(saga.state(), SagaState::Aborted);
+        assert_eq!(saga.completed_steps(), 1);
+        assert_eq!(
+            saga.step_expiry(1),
+            Some(SagaStepExpiry::TimedOut {
+                deadline: Time::from_secs(1)
+            })
+        );
+        let events: Vec<_> = saga.journal().iter().map(|e| (e.step, &e.event)).collect();
+        assert_eq!(
+            events,
+            [
+                (0, &SagaJournalEvent::Started),
+                (0, &SagaJournalEvent::Completed),
+                (1, &SagaJournalEvent::Started),
+                (
+                    1,
+                    &SagaJournalEvent::TimedOut {
+                        deadline: Time::from_secs(1),
+                        detected_at: Time::from_secs(1),
+                    }
+                ),
+                (
+                    0,
+                    &SagaJournalEvent::Compensated {
+                        result: "released reservation".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn saga_timed_step_detects_heartbeat_loss_before_hard_timeout() {
+        let (saga, result, now) = run_timed_saga_in_lab(
+            SagaStepPolicy::new()
+                .with_timeout(Duration::from_secs(10))
+                .with_heartbeat_window(Duration::from_millis(250)),
+            |heartbeat| async move {
+                for _ in 0..3 {
+                    crate::time::sleep(crate::time::wall_now(), Duration::from_millis(100)).await;
+                    heartbeat.beat();
+                }
+                // The remote node dies: no more heartbeats and no result.
+                std::future::pending::<Result<&'static str, String>>().await
+            },
+        );
+
+        assert!(result.is_err());
+        assert_eq!(now, Time::from_millis(550));
+        let expiry = SagaJournalEvent::HeartbeatLost {
+            last_heartbeat: Time::from_millis(300),
+            window: Duration::from_millis(250),
+            detected_at: Time::from_millis(550),
+        };
+        assert_eq!(saga.journal()[3].event, expiry);
+        assert!(matches!(
+            saga.step_expiry(1),
+            Some(SagaStepExpiry::HeartbeatLost { .. })
+        ));
+        assert_eq!(saga.compensation_results().len(), 1);
+        assert_eq!(saga.compensation_results()[0].step, 0);
+        assert!(
+            !saga
+                .journal()
+                .iter()
+                .any(|e| matches!(e.event, SagaJournalEvent::TimedOut { .. }))
+        );
+    }
+
+    #[test]
+    fn saga_rejects_result_delivered_after_timeout() {
+        let (mut saga, result, _) = run_timed_saga_in_lab(
+            SagaStepPolicy::new().with_timeout(Duration::from_millis(500)),
+            |_heartbeat| std::future::pending(),
+        );
+        assert!(result.is_err());
+
+        // The remote charge finally reports success after the saga gave up.
+        let late = RemoteOutcome::Success(b"charged".to_vec());
+        assert!(saga.reject_late_result(1, &late, Time::from_secs(2)));
+        assert_eq!(
+            saga.journal().last().map(|e| &e.event),
+            Some(&SagaJournalEvent::LateResultRejected {
+                outcome: "Success".to_string(),
+                received_at: Time::from_secs(2),
+            })
+        );
+        assert_eq!(saga.state(), SagaState::Aborted);
+        assert_eq!(saga.completed_steps(), 1);
+
+        // Step 0 completed normally; nothing to reject.
+        assert!(!saga.reject_late_result(0, &late, Time::from_secs(2)));
+    }
+
+    #[test]
+    fn saga_timed_step_completing_within_limits_registers_compensation() {
+        let (mut saga, result, _) = run_timed_saga_in_lab(
+            SagaStepPolicy::new()
+                .with_timeout(Duration::from_secs(1))
+                .with_heartbeat_window(Duration::from_millis(300)),
+            |heartbeat| async move {
+                crate::time::sleep(crate::time::wall_now(), Duration::from_millis(200)).await;
+                heartbeat.beat();
+                crate::time::sleep(crate::time::wall_now(), Duration::from_millis(200)).await;
+                Ok("charged")
+            },
+        );
+
+        assert_eq!(result.expect("step completes"), "charged");
+        assert_eq!(saga.completed_steps(), 2);
+        assert_eq!(saga.step_expiry(1), None);
+        saga.abort();
+        let steps: Vec<_> = saga.compensation_results().iter().map(|c| c.step).collect();
+        assert_eq!(steps, [1, 0]);
+    }
+
     // -----------------------------------------------------------------------
     // Invariant tests — lease boundary conditions (B6: asupersync-3narc.2.6)
     // -----------------------------------------------------------------------
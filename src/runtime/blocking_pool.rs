@@ -10,6 +10,7 @@
 //! - **Fairness**: FIFO ordering with priority support
 //! - **Cancellation**: Soft cancellation with completion tracking
 //! - **Shutdown**: Graceful shutdown with bounded drain timeout
+//! - **Lanes**: Optional dedicated CPU lane with independent sizing and queue bounds
 //!
 //! # Design
 //!
@@ -29,6 +30,15 @@
 //! is "soft": the task is marked cancelled, but the blocking closure runs to
 //! completion. The completion notification is suppressed for cancelled tasks.
 //!
+//! ## Lanes
+//!
+//! A pool built with [`BlockingPool::with_cpu_lane`] routes
+//! [`BlockingKind::Cpu`] work to a second, independently sized set of threads,
+//! so long CPU-bound jobs cannot queue ahead of short blocking system calls on
+//! the default [`BlockingKind::Io`] lane. Each lane has its own optional queue
+//! bound, [`BlockingQueueFullPolicy`], idle keep-alive, and
+//! [`BlockingLaneMetrics`]. Without a CPU lane, both kinds share one pool.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -41,7 +51,8 @@
 //! let result = handle.await?;
 //! ```
 
-use crate::runtime::config::BlockingPoolAffinityProfile;
+use crate::observability::metrics::{Histogram, HistogramSnapshot};
+use crate::runtime::config::{BlockingPoolAffinityProfile, BlockingQueueFullPolicy};
 use crate::runtime::pool_sizing::{
     PoolSizingAction, PoolSizingBounds, PoolSizingControllerState, PoolSizingDecision,
     PoolSizingPolicy, PoolSizingTarget, PoolWorkloadEstimate, decide_pool_sizing,
@@ -57,6 +68,12 @@ use std::time::{Duration, Instant};
 /// Default idle timeout before retiring excess threads.
 const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Bucket upper bounds, in seconds, for the queue-wait histogram.
+const QUEUE_WAIT_BUCKETS_SECS: [f64; 8] = [0.000_1, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0];
+
+/// Type-erased blocking closure.
+pub(crate) type BlockingWork = Box<dyn FnOnce() + Send + 'static>;
+
 /// Time source hook used by timeout accounting paths.
 pub type TimeGetter = fn() -> Instant;
 
@@ -108,6 +125,7 @@ fn join_thread_handles(handles: Vec<ThreadJoinHandle<()>>) {
 #[derive(Clone)]
 pub struct BlockingPoolHandle {
     inner: Arc<BlockingPoolInner>,
+    cpu_lane: Option<Arc<BlockingPoolInner>>,
 }
 
 impl fmt::Debug for BlockingPoolHandle {
@@ -121,6 +139,7 @@ impl fmt::Debug for BlockingPoolHandle {
                 "pending_tasks",
                 &self.inner.pending_count.load(Ordering::Relaxed),
             )
+            .field("cpu_lane", &self.cpu_lane.is_some())
             .finish()
     }
 }
@@ -128,6 +147,7 @@ impl fmt::Debug for BlockingPoolHandle {
 /// The blocking pool for executing synchronous operations.
 pub struct BlockingPool {
     inner: Arc<BlockingPoolInner>,
+    cpu_lane: Option<Box<Self>>,
 }
 
 /// Snapshot of blocking-pool affinity activity.
@@ -149,6 +169,77 @@ pub struct BlockingPoolAffinityMetricsSnapshot {
     pub global_pending_count: usize,
 }
 
+/// Which blocking lane a task is submitted to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BlockingKind {
+    /// Long-running CPU-bound work such as compression, hashing, or encoding.
+    Cpu,
+    /// Short blocking system calls such as file metadata or DNS lookups.
+    #[default]
+    Io,
+}
+
+impl BlockingKind {
+    /// Returns a stable lowercase name for metrics and logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Io => "io",
+        }
+    }
+}
+
+/// Error returned when a blocking lane cannot accept a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockingSpawnError {
+    /// The pool has been shut down.
+    Shutdown,
+    /// The lane's queue is at capacity.
+    QueueFull {
+        /// Lane that rejected the task.
+        kind: BlockingKind,
+        /// Configured queue capacity of that lane.
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for BlockingSpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shutdown => write!(f, "blocking pool is shut down"),
+            Self::QueueFull { kind, capacity } => write!(
+                f,
+                "blocking {} lane queue is full (capacity {capacity})",
+                kind.as_str()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockingSpawnError {}
+
+/// Snapshot of one blocking lane's load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockingLaneMetrics {
+    /// Lane the snapshot describes.
+    pub kind: BlockingKind,
+    /// Tasks queued but not yet running.
+    pub queue_depth: usize,
+    /// Configured queue bound, if any.
+    pub queue_capacity: Option<usize>,
+    /// Live worker threads.
+    pub active_threads: usize,
+    /// Worker threads currently executing a task.
+    pub busy_threads: usize,
+    /// Tasks executed to completion (including ones that panicked).
+    pub total_executed: u64,
+    /// Submissions refused because the queue was full.
+    pub total_rejected: u64,
+    /// Time tasks spent queued before a worker picked them up, in seconds.
+    pub queue_wait: HistogramSnapshot,
+}
+
 impl fmt::Debug for BlockingPool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let handles_len = self.inner.thread_handles.lock().len();
@@ -164,6 +255,7 @@ impl fmt::Debug for BlockingPool {
                 &self.inner.pending_count.load(Ordering::Relaxed),
             )
             .field("thread_handles", &handles_len)
+            .field("cpu_lane", &self.cpu_lane.is_some())
             .finish()
     }
 }
@@ -335,6 +427,43 @@ struct BlockingPoolInner {
     on_thread_stop: Option<Arc<dyn Fn() + Send + Sync>>,
     /// Thread join handles for cleanup.
     thread_handles: Mutex<Vec<ThreadJoinHandle<()>>>,
+    /// Queue bound, admission counters, and wait-time histogram.
+    lane: BlockingLaneState,
+}
+
+/// Per-lane queue bound and load accounting.
+struct BlockingLaneState {
+    /// Maximum pending tasks accepted by bounded submissions.
+    queue_capacity: Option<usize>,
+    /// What async submitters do when the queue is full.
+    queue_full_policy: BlockingQueueFullPolicy,
+    /// Tasks executed to completion.
+    total_executed: AtomicU64,
+    /// Submissions refused because the queue was full.
+    total_rejected: AtomicU64,
+    /// Enqueue-to-dequeue wait, in seconds.
+    queue_wait: Histogram,
+}
+
+impl BlockingLaneState {
+    fn from_options(options: &BlockingPoolOptions) -> Self {
+        Self {
+            queue_capacity: options.queue_capacity.map(|capacity| capacity.max(1)),
+            queue_full_policy: options.queue_full_policy,
+            total_executed: AtomicU64::new(0),
+            total_rejected: AtomicU64::new(0),
+            queue_wait: Histogram::new(
+                "blocking_queue_wait_seconds",
+                QUEUE_WAIT_BUCKETS_SECS.to_vec(),
+            ),
+        }
+    }
+}
+
+impl Default for BlockingLaneState {
+    fn default() -> Self {
+        Self::from_options(&BlockingPoolOptions::default())
+    }
 }
 
 struct BlockingPoolAffinityState {
@@ -368,6 +497,8 @@ struct BlockingTask {
     cancelled: Arc<AtomicBool>,
     /// Completion signal.
     completion: Arc<BlockingTaskCompletion>,
+    /// When the task entered the queue, for queue-wait accounting.
+    enqueued_at: Instant,
 }
 
 /// Completion tracking for a blocking task.
@@ -530,6 +661,7 @@ impl BlockingPool {
         );
 
         let affinity = BlockingPoolAffinityState::from_options(&options);
+        let lane = BlockingLaneState::from_options(&options);
         let inner = Arc::new(BlockingPoolInner {
             min_threads,
             max_threads,
@@ -552,9 +684,13 @@ impl BlockingPool {
             on_thread_start: options.on_thread_start,
             on_thread_stop: options.on_thread_stop,
             thread_handles: Mutex::new(Vec::with_capacity(max_threads)),
+            lane,
         });
 
-        let pool = Self { inner };
+        let pool = Self {
+            inner,
+            cpu_lane: None,
+        };
 
         // Spawn minimum threads eagerly
         for _ in 0..min_threads {
@@ -569,9 +705,66 @@ impl BlockingPool {
     pub fn handle(&self) -> BlockingPoolHandle {
         BlockingPoolHandle {
             inner: Arc::clone(&self.inner),
+            cpu_lane: self.cpu_lane.as_ref().map(|lane| Arc::clone(&lane.inner)),
         }
     }
 
+    /// Attaches a dedicated lane for [`BlockingKind::Cpu`] work.
+    ///
+    /// This pool keeps serving [`BlockingKind::Io`] work. Shutdown and drain
+    /// cover both lanes; the lanes should share a time source so
+    /// [`shutdown_and_wait`](Self::shutdown_and_wait) budgets line up.
+    /// Handles obtained before this call do not see the new lane.
+    #[must_use]
+    pub fn with_cpu_lane(mut self, lane: Self) -> Self {
+        self.cpu_lane = Some(Box::new(lane));
+        self
+    }
+
+    /// Returns `true` if CPU-bound work runs on a dedicated lane.
+    #[must_use]
+    pub fn has_cpu_lane(&self) -> bool {
+        self.cpu_lane.is_some()
+    }
+
+    /// Spawns a blocking task on the lane for `kind`.
+    ///
+    /// Like [`spawn`](Self::spawn), this never applies the lane's queue bound;
+    /// use [`try_spawn_kind`](Self::try_spawn_kind) for bounded admission.
+    pub fn spawn_kind<F>(&self, kind: BlockingKind, f: F) -> BlockingTaskHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.handle().spawn_kind(kind, f)
+    }
+
+    /// Spawns a blocking task on the lane for `kind`, respecting its queue bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BlockingSpawnError::QueueFull`] when the lane's queue is at
+    /// capacity and [`BlockingSpawnError::Shutdown`] after shutdown.
+    pub fn try_spawn_kind<F>(
+        &self,
+        kind: BlockingKind,
+        f: F,
+    ) -> Result<BlockingTaskHandle, BlockingSpawnError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.handle().try_spawn_kind(kind, f)
+    }
+
+    /// Returns a load snapshot for the lane serving `kind`.
+    #[must_use]
+    pub fn lane_metrics(&self, kind: BlockingKind) -> BlockingLaneMetrics {
+        let inner = match (kind, self.cpu_lane.as_ref()) {
+            (BlockingKind::Cpu, Some(lane)) => &lane.inner,
+            _ => &self.inner,
+        };
+        blocking_lane_metrics(inner, kind)
+    }
+
     /// Spawns a blocking task.
     ///
     /// The closure will be executed on a blocking pool thread.
@@ -637,6 +830,7 @@ impl BlockingPool {
             preferred_cohort,
             cancelled: Arc::clone(&cancelled),
             completion: Arc::clone(&completion),
+            enqueued_at: (self.inner.time_getter)(),
         };
 
         if !try_enqueue_task(&self.inner, task) {
@@ -738,9 +932,14 @@ impl BlockingPool {
     ///
     /// No new tasks will be accepted. Pending tasks will continue to execute.
     pub fn shutdown(&self) {
-        let _guard = self.inner.mutex.lock();
-        self.inner.shutdown.store(true, Ordering::Release);
-        self.inner.condvar.notify_all();
+        {
+            let _guard = self.inner.mutex.lock();
+            self.inner.shutdown.store(true, Ordering::Release);
+            self.inner.condvar.notify_all();
+        }
+        if let Some(lane) = self.cpu_lane.as_ref() {
+            lane.shutdown();
+        }
     }
 
     /// Shuts down and waits for all threads to exit.
@@ -752,11 +951,21 @@ impl BlockingPool {
     /// # Returns
     ///
     /// `true` if all threads exited cleanly, `false` if timeout elapsed.
+    ///
+    /// With a CPU lane attached, both lanes drain within the same `timeout`.
     pub fn shutdown_and_wait(&self, timeout: Duration) -> bool {
         self.shutdown();
 
         let deadline = timeout_deadline(timeout, self.inner.time_getter);
+        let mut clean = self.wait_for_workers(deadline);
+        if let Some(lane) = self.cpu_lane.as_ref() {
+            clean &= lane.wait_for_workers(deadline);
+        }
+        clean
+    }
 
+    /// Waits until every worker of this lane has exited, then joins them.
+    fn wait_for_workers(&self, deadline: Instant) -> bool {
         // Wait until no worker is active AND no retiring worker has a
         // replacement hand-off in flight. Every retiring worker announces a
         // possible hand-off in `replacement_pending` *before* it decrements
@@ -829,6 +1038,120 @@ impl BlockingPoolHandle {
         self.spawn_with_affinity(f, 128, None)
     }
 
+    /// Spawns a blocking task on the lane for `kind`, ignoring queue bounds.
+    ///
+    /// See [`BlockingPool::spawn_kind`].
+    pub fn spawn_kind<F>(&self, kind: BlockingKind, f: F) -> BlockingTaskHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        match (kind, self.cpu_lane.as_ref()) {
+            (BlockingKind::Cpu, Some(lane)) => Self {
+                inner: Arc::clone(lane),
+                cpu_lane: None,
+            }
+            .spawn(f),
+            _ => self.spawn(f),
+        }
+    }
+
+    /// Spawns a blocking task on the lane for `kind`, respecting its queue bound.
+    ///
+    /// # Errors
+    ///
+    /// See [`BlockingPool::try_spawn_kind`].
+    pub fn try_spawn_kind<F>(
+        &self,
+        kind: BlockingKind,
+        f: F,
+    ) -> Result<BlockingTaskHandle, BlockingSpawnError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.try_submit(kind, Box::new(f))
+            .map_err(|(error, _work)| {
+                self.note_rejected(error);
+                error
+            })
+    }
+
+    /// Returns `true` if CPU-bound work runs on a dedicated lane.
+    #[must_use]
+    pub fn has_cpu_lane(&self) -> bool {
+        self.cpu_lane.is_some()
+    }
+
+    /// Returns the queue-full policy of the lane serving `kind`.
+    #[must_use]
+    pub fn queue_full_policy(&self, kind: BlockingKind) -> BlockingQueueFullPolicy {
+        self.lane_inner(kind).lane.queue_full_policy
+    }
+
+    /// Returns a load snapshot for the lane serving `kind`.
+    #[must_use]
+    pub fn lane_metrics(&self, kind: BlockingKind) -> BlockingLaneMetrics {
+        blocking_lane_metrics(self.lane_inner(kind), kind)
+    }
+
+    fn lane_inner(&self, kind: BlockingKind) -> &Arc<BlockingPoolInner> {
+        match (kind, self.cpu_lane.as_ref()) {
+            (BlockingKind::Cpu, Some(lane)) => lane,
+            _ => &self.inner,
+        }
+    }
+
+    /// Submits `work` to the lane for `kind`, respecting its queue bound.
+    ///
+    /// On refusal the work is handed back so the caller can retry it.
+    pub(crate) fn try_submit(
+        &self,
+        kind: BlockingKind,
+        work: BlockingWork,
+    ) -> Result<BlockingTaskHandle, (BlockingSpawnError, BlockingWork)> {
+        let inner = self.lane_inner(kind);
+        let task_id = inner.next_task_id.fetch_add(1, Ordering::Relaxed);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let completion = Arc::new(BlockingTaskCompletion::new(inner.time_getter));
+        let handle = BlockingTaskHandle {
+            task_id,
+            cancelled: Arc::clone(&cancelled),
+            completion: Arc::clone(&completion),
+        };
+        let task = BlockingTask {
+            work,
+            priority: 128,
+            preferred_cohort: None,
+            cancelled,
+            completion,
+            enqueued_at: (inner.time_getter)(),
+        };
+
+        if let Err((refusal, task)) = try_enqueue_bounded_task(inner, task) {
+            let error = match refusal {
+                EnqueueRefusal::Shutdown => BlockingSpawnError::Shutdown,
+                EnqueueRefusal::Full(capacity) => BlockingSpawnError::QueueFull { kind, capacity },
+            };
+            return Err((error, task.work));
+        }
+
+        maybe_spawn_thread_on_inner(inner);
+        {
+            let _guard = inner.mutex.lock();
+            inner.condvar.notify_one();
+        }
+        Ok(handle)
+    }
+
+    /// Counts a queue-full refusal that was surfaced to the submitter.
+    pub(crate) fn note_rejected(&self, error: BlockingSpawnError) {
+        if let BlockingSpawnError::QueueFull { kind, .. } = error {
+            self.lane_inner(kind)
+                .lane
+                .total_rejected
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
     /// Spawns a blocking task with a preferred cohort for locality-biased routing.
     pub fn spawn_on_cohort<F>(&self, cohort: usize, f: F) -> BlockingTaskHandle
     where
@@ -876,6 +1199,7 @@ impl BlockingPoolHandle {
             preferred_cohort,
             cancelled: Arc::clone(&cancelled),
             completion: Arc::clone(&completion),
+            enqueued_at: (self.inner.time_getter)(),
         };
 
         if !try_enqueue_task(&self.inner, task) {
@@ -970,24 +1294,68 @@ impl BlockingPoolHandle {
     }
 }
 
+/// Why a bounded enqueue was refused.
+enum EnqueueRefusal {
+    Shutdown,
+    Full(usize),
+}
+
+/// Enqueues `task` unless the pool is shut down or the lane's queue bound is
+/// reached, handing the task back on refusal. The bound is checked under the
+/// submission lock, so concurrent submitters cannot overshoot it.
+fn try_enqueue_bounded_task(
+    inner: &Arc<BlockingPoolInner>,
+    task: BlockingTask,
+) -> Result<(), (EnqueueRefusal, BlockingTask)> {
+    let _guard = inner.mutex.lock();
+    if inner.shutdown.load(Ordering::Acquire) {
+        return Err((EnqueueRefusal::Shutdown, task));
+    }
+    if let Some(capacity) = inner.lane.queue_capacity {
+        if inner.pending_count.load(Ordering::Acquire) >= capacity {
+            return Err((EnqueueRefusal::Full(capacity), task));
+        }
+    }
+    enqueue_task_locked(inner, task);
+    Ok(())
+}
+
 fn try_enqueue_task(inner: &Arc<BlockingPoolInner>, task: BlockingTask) -> bool {
     let _guard = inner.mutex.lock();
     if inner.shutdown.load(Ordering::Acquire) {
         return false;
     }
+    enqueue_task_locked(inner, task);
+    true
+}
+
+/// Pushes `task` onto its queue. Callers hold `inner.mutex`.
+fn enqueue_task_locked(inner: &BlockingPoolInner, task: BlockingTask) {
     if let Some(affinity) = inner.affinity.as_ref() {
         match affinity.route_task(&inner.pending_count, task) {
-            Ok(()) => return true,
+            Ok(()) => return,
             Err(task) => {
                 inner.queue.push(task);
                 inner.pending_count.fetch_add(1, Ordering::Relaxed);
-                return true;
+                return;
             }
         }
     }
     inner.queue.push(task);
     inner.pending_count.fetch_add(1, Ordering::Relaxed);
-    true
+}
+
+fn blocking_lane_metrics(inner: &BlockingPoolInner, kind: BlockingKind) -> BlockingLaneMetrics {
+    BlockingLaneMetrics {
+        kind,
+        queue_depth: inner.pending_count.load(Ordering::Relaxed),
+        queue_capacity: inner.lane.queue_capacity,
+        active_threads: inner.active_threads.load(Ordering::Relaxed),
+        busy_threads: inner.busy_threads.load(Ordering::Relaxed),
+        total_executed: inner.lane.total_executed.load(Ordering::Relaxed),
+        total_rejected: inner.lane.total_rejected.load(Ordering::Relaxed),
+        queue_wait: inner.lane.queue_wait.snapshot(),
+    }
 }
 
 fn blocking_pool_has_pending_work(inner: &BlockingPoolInner) -> bool {
@@ -1149,6 +1517,12 @@ pub struct BlockingPoolOptions {
     pub affinity_profile: BlockingPoolAffinityProfile,
     /// Number of scheduler cohorts available for blocking-pool routing.
     pub cohort_count: Option<usize>,
+    /// Maximum pending tasks accepted by bounded submissions
+    /// ([`BlockingPool::try_spawn_kind`] and lane-aware `spawn_blocking`).
+    /// `None` leaves the queue unbounded.
+    pub queue_capacity: Option<usize>,
+    /// What async submitters do when `queue_capacity` is reached.
+    pub queue_full_policy: BlockingQueueFullPolicy,
 }
 
 impl Default for BlockingPoolOptions {
@@ -1162,6 +1536,8 @@ impl Default for BlockingPoolOptions {
             on_thread_stop: None,
             affinity_profile: BlockingPoolAffinityProfile::Disabled,
             cohort_count: None,
+            queue_capacity: None,
+            queue_full_policy: BlockingQueueFullPolicy::Wait,
         }
     }
 }
@@ -1183,6 +1559,8 @@ impl fmt::Debug for BlockingPoolOptions {
             .field("on_thread_stop", &self.on_thread_stop.is_some())
             .field("affinity_profile", &self.affinity_profile)
             .field("cohort_count", &self.cohort_count)
+            .field("queue_capacity", &self.queue_capacity)
+            .field("queue_full_policy", &self.queue_full_policy)
             .finish()
     }
 }
@@ -1234,9 +1612,9 @@ fn spawn_thread_on_inner(inner: &Arc<BlockingPoolInner>) {
         impl Drop for ThreadExitGuard<'_> {
             fn drop(&mut self) {
                 if let Some(ref callback) = self.inner.on_thread_stop {
-                    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    let _ = crate::runtime::panic_strategy::catch_unwind(|| {
                         callback();
-                    }));
+                    });
                 }
 
                 if !self.retired_with_claim {
@@ -1381,6 +1759,8 @@ fn blocking_worker_loop(inner: &BlockingPoolInner, assigned_cohort: Option<usize
 
             inner.pending_count.fetch_sub(1, Ordering::Relaxed);
             inner.busy_threads.fetch_add(1, Ordering::Relaxed);
+            let waited = (inner.time_getter)().saturating_duration_since(task.enqueued_at);
+            inner.lane.queue_wait.observe(waited.as_secs_f64());
 
             // Check if task was cancelled before execution
             if task.cancelled.load(Ordering::Acquire) {
@@ -1394,8 +1774,9 @@ fn blocking_worker_loop(inner: &BlockingPoolInner, assigned_cohort: Option<usize
             // which would cause waiters to hang indefinitely and the
             // worker thread to die (losing on_thread_stop + active_threads
             // decrement).
-            let _result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(task.work));
+            let _result = crate::runtime::panic_strategy::catch_unwind(task.work);
             inner.busy_threads.fetch_sub(1, Ordering::Relaxed);
+            inner.lane.total_executed.fetch_add(1, Ordering::Relaxed);
 
             // Always signal completion so waiters are unblocked, even
             // if the task panicked.
@@ -1577,6 +1958,7 @@ mod tests {
             preferred_cohort,
             cancelled: Arc::new(AtomicBool::new(false)),
             completion: Arc::new(BlockingTaskCompletion::new(wall_clock_now)),
+            enqueued_at: wall_clock_now(),
         }
     }
 
@@ -1611,6 +1993,7 @@ mod tests {
             on_thread_start: None,
             on_thread_stop: None,
             thread_handles: Mutex::new(Vec::new()),
+            lane: BlockingLaneState::default(),
         })
     }
 
@@ -2350,6 +2733,7 @@ mod tests {
             on_thread_start: None,
             on_thread_stop: None,
             thread_handles: Mutex::new(Vec::new()),
+            lane: BlockingLaneState::default(),
         });
 
         let barrier = Arc::new(std::sync::Barrier::new(3));
@@ -2456,6 +2840,7 @@ mod tests {
             on_thread_start: None,
             on_thread_stop: None,
             thread_handles: Mutex::new(Vec::new()),
+            lane: BlockingLaneState::default(),
         });
 
         // Already at max_threads (2), spawn should be a no-op
@@ -2496,6 +2881,7 @@ mod tests {
             on_thread_start: None,
             on_thread_stop: None,
             thread_handles: Mutex::new(Vec::new()),
+            lane: BlockingLaneState::default(),
         });
 
         // Try to spawn when already at max
@@ -2586,6 +2972,211 @@ mod tests {
         );
     }
 
+    fn lane_gate() -> Arc<(StdMutex<bool>, StdCondvar)> {
+        Arc::new((StdMutex::new(false), StdCondvar::new()))
+    }
+
+    fn open_lane_gate(gate: &(StdMutex<bool>, StdCondvar)) {
+        let (lock, condvar) = gate;
+        *lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = true;
+        condvar.notify_all();
+    }
+
+    fn wait_lane_gate(gate: &(StdMutex<bool>, StdCondvar)) {
+        let (lock, condvar) = gate;
+        let open = lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let _open = condvar
+            .wait_timeout_while(open, Duration::from_secs(30), |open| !*open)
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+    }
+
+    fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !condition() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+
+    fn lane_pool(queue_capacity: Option<usize>, policy: BlockingQueueFullPolicy) -> BlockingPool {
+        BlockingPool::with_config(
+            1,
+            1,
+            BlockingPoolOptions {
+                queue_capacity,
+                queue_full_policy: policy,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn saturated_cpu_lane_does_not_starve_io_lane() {
+        let pool = lane_pool(None, BlockingQueueFullPolicy::Wait)
+            .with_cpu_lane(lane_pool(None, BlockingQueueFullPolicy::Wait));
+        assert!(pool.has_cpu_lane());
+
+        let gate = lane_gate();
+        let cpu_handles: Vec<_> = (0..4)
+            .map(|_| {
+                let gate = Arc::clone(&gate);
+                pool.spawn_kind(BlockingKind::Cpu, move || wait_lane_gate(&gate))
+            })
+            .collect();
+        assert!(wait_until(|| pool
+            .lane_metrics(BlockingKind::Cpu)
+            .busy_threads
+            == 1));
+
+        let io = pool.spawn_kind(BlockingKind::Io, || {});
+        assert!(
+            io.wait_timeout(Duration::from_secs(30)),
+            "io work must complete while the cpu lane is saturated"
+        );
+        assert_eq!(pool.lane_metrics(BlockingKind::Io).total_executed, 1);
+        assert_eq!(pool.lane_metrics(BlockingKind::Cpu).total_executed, 0);
+
+        open_lane_gate(&gate);
+        for handle in &cpu_handles {
+            assert!(handle.wait_timeout(Duration::from_secs(30)));
+        }
+        assert!(pool.shutdown_and_wait(Duration::from_secs(30)));
+        assert_eq!(pool.lane_metrics(BlockingKind::Cpu).total_executed, 4);
+    }
+
+    #[test]
+    fn full_lane_rejects_with_typed_error() {
+        let pool = BlockingPool::new(1, 1)
+            .with_cpu_lane(lane_pool(Some(1), BlockingQueueFullPolicy::Reject));
+        assert_eq!(
+            pool.handle().queue_full_policy(BlockingKind::Cpu),
+            BlockingQueueFullPolicy::Reject
+        );
+
+        let gate = lane_gate();
+        let running = {
+            let gate = Arc::clone(&gate);
+            pool.try_spawn_kind(BlockingKind::Cpu, move || wait_lane_gate(&gate))
+                .expect("idle lane accepts work")
+        };
+        assert!(wait_until(|| pool
+            .lane_metrics(BlockingKind::Cpu)
+            .busy_threads
+            == 1));
+
+        let queued = pool
+            .try_spawn_kind(BlockingKind::Cpu, || {})
+            .expect("one slot is free");
+        let rejected = pool
+            .try_spawn_kind(BlockingKind::Cpu, || panic!("must not run"))
+            .expect_err("lane queue is full");
+        assert_eq!(
+            rejected,
+            BlockingSpawnError::QueueFull {
+                kind: BlockingKind::Cpu,
+                capacity: 1,
+            }
+        );
+
+        // The unbounded io lane is unaffected.
+        assert!(pool.try_spawn_kind(BlockingKind::Io, || {}).is_ok());
+
+        let metrics = pool.lane_metrics(BlockingKind::Cpu);
+        assert_eq!(metrics.queue_depth, 1);
+        assert_eq!(metrics.queue_capacity, Some(1));
+        assert_eq!(metrics.total_rejected, 1);
+
+        open_lane_gate(&gate);
+        assert!(running.wait_timeout(Duration::from_secs(30)));
+        assert!(queued.wait_timeout(Duration::from_secs(30)));
+        assert!(pool.shutdown_and_wait(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn cpu_lane_reaps_idle_threads_independently() {
+        let _guard = deterministic_hook_test_guard();
+        reset_scripted_time_state();
+
+        let retired = lane_gate();
+        let retired_signal = Arc::clone(&retired);
+        let cpu = BlockingPool::with_config(
+            0,
+            1,
+            BlockingPoolOptions {
+                idle_timeout: Duration::from_millis(5),
+                time_getter: stepped_timeout_time,
+                on_thread_stop: Some(Arc::new(move || open_lane_gate(&retired_signal))),
+                ..Default::default()
+            },
+        );
+        let pool = BlockingPool::new(1, 1).with_cpu_lane(cpu);
+
+        pool.spawn_kind(BlockingKind::Cpu, || {}).wait();
+        wait_lane_gate(&retired);
+
+        assert!(wait_until(|| pool
+            .lane_metrics(BlockingKind::Cpu)
+            .active_threads
+            == 0));
+        assert_eq!(
+            pool.lane_metrics(BlockingKind::Io).active_threads,
+            1,
+            "io lane keeps its minimum thread while the cpu lane reaps"
+        );
+        assert!(pool.shutdown_and_wait(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn shutdown_and_wait_drains_both_lanes() {
+        let pool = lane_pool(None, BlockingQueueFullPolicy::Wait)
+            .with_cpu_lane(lane_pool(None, BlockingQueueFullPolicy::Wait));
+        let executed = Arc::new(AtomicUsize::new(0));
+
+        for kind in [BlockingKind::Cpu, BlockingKind::Io] {
+            for _ in 0..8 {
+                let executed = Arc::clone(&executed);
+                pool.spawn_kind(kind, move || {
+                    executed.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        }
+
+        assert!(
+            pool.shutdown_and_wait(Duration::from_secs(30)),
+            "both lanes should drain within the shared budget"
+        );
+        assert_eq!(executed.load(Ordering::Relaxed), 16);
+        for kind in [BlockingKind::Cpu, BlockingKind::Io] {
+            let metrics = pool.lane_metrics(kind);
+            assert_eq!(metrics.kind, kind);
+            assert_eq!(metrics.queue_depth, 0);
+            assert_eq!(metrics.active_threads, 0);
+            assert_eq!(metrics.total_executed, 8);
+            assert_eq!(metrics.queue_wait.count, 8);
+        }
+    }
+
+    #[test]
+    fn lane_requests_without_cpu_lane_use_io_pool() {
+        let pool = BlockingPool::new(1, 1);
+        assert!(!pool.has_cpu_lane());
+
+        pool.spawn_kind(BlockingKind::Cpu, || {}).wait();
+        assert_eq!(pool.lane_metrics(BlockingKind::Io).total_executed, 1);
+        assert!(pool.shutdown_and_wait(Duration::from_secs(30)));
+        assert_eq!(
+            pool.try_spawn_kind(BlockingKind::Io, || {}).unwrap_err(),
+            BlockingSpawnError::Shutdown
+        );
+    }
+
     #[test]
     fn shutdown_and_wait_uses_custom_time_and_sleep_hooks() {
         let _guard = deterministic_hook_test_guard();
@@ -2799,6 +3390,7 @@ mod tests {
             preferred_cohort: None,
             cancelled: Arc::clone(&cancelled),
             completion: Arc::clone(&completion),
+            enqueued_at: (pool.inner.time_getter)(),
         };
 
         pool.inner.queue.push(task);
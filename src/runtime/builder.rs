@@ -119,16 +119,23 @@
 //! | [`steal_batch_size`](RuntimeBuilder::steal_batch_size) | 16 | Work-stealing batch size |
 //! | [`adaptive_ready_batch`](RuntimeBuilder::adaptive_ready_batch) | disabled | Observe-first adaptive ready-lane batch sizing |
 //! | [`blocking_threads`](RuntimeBuilder::blocking_threads) | 0, 0 | Blocking pool min/max |
+//! | [`blocking_queue`](RuntimeBuilder::blocking_queue) | unbounded, wait | I/O blocking lane queue bound and full policy |
+//! | [`blocking_cpu_lane`](RuntimeBuilder::blocking_cpu_lane) | none | Dedicated lane for CPU-bound blocking work |
 //! | [`enable_parking`](RuntimeBuilder::enable_parking) | true | Park idle workers |
 //! | [`poll_budget`](RuntimeBuilder::poll_budget) | 128 | Polls before cooperative yield |
 //! | [`capacity_hints`](RuntimeBuilder::capacity_hints) | auto from `worker_threads` | Initial task/region/obligation table sizing |
 //! | [`expected_concurrent_tasks`](RuntimeBuilder::expected_concurrent_tasks) | unset | Burst-tolerant task-capacity shortcut with 50% headroom |
+//! | [`object_pool_max_per_shard`](RuntimeBuilder::object_pool_max_per_shard) | 256 | Recycled task records kept per worker shard |
 //! | [`browser_ready_handoff_limit`](RuntimeBuilder::browser_ready_handoff_limit) | 0 (disabled) | Max ready dispatch burst before host-turn handoff |
 //! | [`browser_worker_offload`](RuntimeBuilder::browser_worker_offload) | disabled | Browser worker offload policy contract |
 //! | [`cancel_lane_max_streak`](RuntimeBuilder::cancel_lane_max_streak) | 16 | Max consecutive cancel dispatches |
 //! | [`enable_adaptive_cancel_streak`](RuntimeBuilder::enable_adaptive_cancel_streak) | true | Enable regret-bounded adaptive cancel streak |
 //! | [`adaptive_cancel_streak_epoch_steps`](RuntimeBuilder::adaptive_cancel_streak_epoch_steps) | 128 | Dispatches per adaptive epoch |
 //! | [`root_region_limits`](RuntimeBuilder::root_region_limits) | None | Admission limits for the root region |
+//! | [`max_live_tasks`](RuntimeBuilder::max_live_tasks) | None | Runtime-wide live-task limit |
+//! | [`spawn_soft_watermark`](RuntimeBuilder::spawn_soft_watermark) | None | Live tasks that raise spawn pressure |
+//! | [`region_fairness`](RuntimeBuilder::region_fairness) | `TaskFair` | Share ready dispatches between regions by weight |
+//! | [`default_region_weight`](RuntimeBuilder::default_region_weight) | 1024 | Weight of regions without an explicit one |
 //! | [`observability`](RuntimeBuilder::observability) | None | Attach structured logging collectors |
 //!
 //! # Error Handling
@@ -165,15 +172,21 @@ use crate::runtime::deadline_monitor::{
     AdaptiveDeadlineConfig, DeadlineTaskSnapshot, DeadlineWarning, MonitorConfig,
     default_warning_handler,
 };
+use crate::runtime::embedded::EmbeddedDriver;
 use crate::runtime::io_driver::IoDriverHandle;
+use crate::runtime::panic_strategy::CrashReportConfig;
 use crate::runtime::reactor::Reactor;
 use crate::runtime::resource_monitor::ResourceMonitor;
 use crate::runtime::scheduler::three_lane::AdaptiveBatchSizingProfile;
-use crate::runtime::scheduler::{ThreeLaneScheduler, ThreeLaneWorker};
+use crate::runtime::scheduler::{
+    RegionFairness, RegionVruntime, ThreeLaneScheduler, ThreeLaneWorker,
+};
+use crate::runtime::shutdown_report::ShutdownReport;
+use crate::runtime::spawn_capacity::{SpawnCapacityConfig, SpawnCapacityGate, SpawnCapacityMetrics};
 use crate::time::TimerDriverHandle;
 use crate::trace::distributed::LogicalClockMode;
-use crate::types::{Budget, CancelAttributionConfig};
-use crate::util::EntropySource;
+use crate::types::{Budget, CancelAttributionConfig, CancelReason};
+use crate::util::{EntropySource, SeededStreams};
 #[cfg(target_arch = "wasm32")]
 use js_sys::{Reflect, global};
 use parking_lot::{Mutex, MutexGuard};
@@ -208,12 +221,12 @@ thread_local! {
 }
 
 /// RAII guard that installs (and restores) a thread-local [`RuntimeHandle`].
-struct ScopedRuntimeHandle {
+pub(crate) struct ScopedRuntimeHandle {
     prev: Option<RuntimeHandle>,
 }
 
 impl ScopedRuntimeHandle {
-    fn new(handle: RuntimeHandle) -> Self {
+    pub(crate) fn new(handle: RuntimeHandle) -> Self {
         let prev = CURRENT_RUNTIME_HANDLE.with(|cell| cell.replace(Some(handle)));
         Self { prev }
     }
@@ -232,6 +245,7 @@ impl Drop for ScopedRuntimeHandle {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RuntimeHostServicesKind {
     NativeStdThread,
+    EmbeddedHostLoop,
 }
 
 #[allow(dead_code)] // Used on wasm32 target
@@ -239,6 +253,7 @@ impl RuntimeHostServicesKind {
     const fn as_str(self) -> &'static str {
         match self {
             Self::NativeStdThread => "native-std-thread",
+            Self::EmbeddedHostLoop => "embedded-host-loop",
         }
     }
 }
@@ -450,6 +465,42 @@ impl RuntimeHostServices for NativeThreadHostServices {
     }
 }
 
+/// Host services for [`RuntimeBuilder::build_embedded`]: the worker is kept
+/// for the host's [`EmbeddedDriver`] instead of getting its own thread.
+#[derive(Default)]
+struct EmbeddedHostServices {
+    workers: Mutex<Vec<ThreeLaneWorker>>,
+}
+
+impl EmbeddedHostServices {
+    fn take_worker(&self) -> Option<ThreeLaneWorker> {
+        lock_state(&self.workers).pop()
+    }
+}
+
+impl RuntimeHostServices for EmbeddedHostServices {
+    fn kind(&self) -> RuntimeHostServicesKind {
+        RuntimeHostServicesKind::EmbeddedHostLoop
+    }
+
+    fn spawn_workers(
+        &self,
+        _runtime: &Arc<RuntimeInner>,
+        workers: Vec<ThreeLaneWorker>,
+    ) -> io::Result<Vec<std::thread::JoinHandle<()>>> {
+        *lock_state(&self.workers) = workers;
+        Ok(Vec::new())
+    }
+
+    fn start_deadline_monitor(
+        &self,
+        config: &RuntimeConfig,
+        state: &Arc<crate::sync::ContendedMutex<RuntimeState>>,
+    ) -> DeadlineMonitorHostService {
+        NativeThreadHostServices::start_deadline_monitor(config, state)
+    }
+}
+
 fn default_runtime_host_services() -> Arc<dyn RuntimeHostServices> {
     Arc::new(NativeThreadHostServices::new())
 }
@@ -2204,6 +2255,7 @@ pub struct RuntimeBuilder {
     timer_driver: Option<TimerDriverHandle>,
     entropy_source: Option<Arc<dyn EntropySource>>,
     host_services: Arc<dyn RuntimeHostServices>,
+    chaos: Option<crate::runtime::chaos::NativeChaosConfig>,
 }
 
 impl RuntimeBuilder {
@@ -2218,6 +2270,7 @@ impl RuntimeBuilder {
             timer_driver: None,
             entropy_source: None,
             host_services: default_runtime_host_services(),
+            chaos: None,
         }
     }
 
@@ -2310,6 +2363,39 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Set the number of I/O reactors (default: 1, `0` is treated as 1).
+    ///
+    /// The runtime's reactor is polled by the workers as usual; each
+    /// additional platform reactor gets a dedicated `asupersync-reactor-<i>`
+    /// poll thread, joined when the runtime drops. New I/O registrations are
+    /// spread round-robin; sockets pinned with
+    /// [`TcpSocket::set_reactor_affinity`](crate::net::TcpSocket::set_reactor_affinity)
+    /// land on the chosen reactor. Has no effect when no platform reactor is
+    /// attached or an explicit [`with_io_driver`](Self::with_io_driver) is
+    /// supplied.
+    #[must_use]
+    pub fn reactors(mut self, count: usize) -> Self {
+        self.config.reactor_count = count;
+        self
+    }
+
+    /// Choose the platform reactor backend (default: native with `poll(2)`
+    /// fallback).
+    ///
+    /// [`ReactorBackendPreference::Poll`](crate::runtime::reactor::ReactorBackendPreference::Poll)
+    /// forces the portable `poll(2)` reactor, which is slower with many
+    /// registrations but runs on any Unix. `ASUPERSYNC_REACTOR_BACKEND`
+    /// overrides this choice at build time. Has no effect when a reactor or
+    /// I/O driver is injected explicitly.
+    #[must_use]
+    pub fn reactor_backend(
+        mut self,
+        preference: crate::runtime::reactor::ReactorBackendPreference,
+    ) -> Self {
+        self.config.reactor_backend = preference;
+        self
+    }
+
     /// Set the observe-first adaptive ready-lane batch sizing profile.
     ///
     /// The default profile is disabled, preserving fixed `steal_batch_size`
@@ -2329,6 +2415,44 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Set how far a `timeout()` may overshoot the ambient deadline before
+    /// its clamp is logged as a warning.
+    ///
+    /// Defaults to [`DEFAULT_DEADLINE_CLAMP_SLACK`](crate::time::DEFAULT_DEADLINE_CLAMP_SLACK).
+    #[must_use]
+    pub fn deadline_clamp_slack(mut self, slack: Duration) -> Self {
+        self.config.deadline_clamp_slack = slack;
+        self
+    }
+
+    /// Seed the task random streams behind [`Cx::rng`](crate::cx::Cx::rng)
+    /// and [`Cx::rng_named`](crate::cx::Cx::rng_named) with a fixed value.
+    ///
+    /// By default each task's streams are seeded from OS entropy. A fixed
+    /// seed makes a task's draws depend only on the seed and the task's
+    /// identity, so a production failure can be re-run with the same
+    /// application randomness. Raw entropy draws such as
+    /// [`Cx::random_bytes`](crate::cx::Cx::random_bytes) keep using the
+    /// configured entropy source.
+    #[must_use]
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.config.rng_seed = Some(seed);
+        self
+    }
+
+    /// Install a capability-usage audit on every task context.
+    ///
+    /// The audit records the first use of each attenuable capability in each
+    /// region against the capabilities its manifest declares. In
+    /// [`CapabilityAuditMode::Enforce`](crate::cx::CapabilityAuditMode::Enforce)
+    /// an undeclared use fails with
+    /// [`CapabilityDenied`](crate::cx::CapabilityDenied).
+    #[must_use]
+    pub fn capability_audit(mut self, audit: Arc<crate::cx::CapabilityAudit>) -> Self {
+        self.config.capability_audit = Some(audit);
+        self
+    }
+
     /// Set cancellation attribution chain limits.
     #[must_use]
     pub fn cancel_attribution_config(mut self, config: CancelAttributionConfig) -> Self {
@@ -2344,6 +2468,38 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Bound the default (I/O) blocking lane's queue.
+    ///
+    /// When `capacity` tasks are already queued, `policy` decides whether
+    /// lane-aware submitters wait for room or fail with a typed rejection.
+    #[must_use]
+    pub fn blocking_queue(
+        mut self,
+        capacity: usize,
+        policy: crate::runtime::config::BlockingQueueFullPolicy,
+    ) -> Self {
+        self.config.blocking.queue_capacity = Some(capacity);
+        self.config.blocking.queue_full_policy = policy;
+        self
+    }
+
+    /// Set how long idle I/O-lane blocking threads above the minimum are kept.
+    #[must_use]
+    pub fn blocking_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.config.blocking.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Give CPU-bound blocking work its own lane.
+    ///
+    /// Work submitted with [`BlockingKind::Cpu`](crate::runtime::BlockingKind::Cpu)
+    /// then runs on these threads and cannot starve I/O-lane work.
+    #[must_use]
+    pub fn blocking_cpu_lane(mut self, lane: crate::runtime::config::BlockingLaneConfig) -> Self {
+        self.config.blocking.cpu_lane = Some(lane);
+        self
+    }
+
     /// Configure cohort-aware affinity routing for the blocking pool.
     #[must_use]
     pub fn blocking_affinity_profile(
@@ -2406,6 +2562,23 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Bound the number of recycled task records each worker shard keeps.
+    ///
+    /// Spawns beyond the pooled supply fall back to the allocator, and
+    /// releases into a full shard drop the record. `0` disables pooling.
+    #[must_use]
+    pub fn object_pool_max_per_shard(mut self, max_pooled: usize) -> Self {
+        self.config.object_pools.max_pooled_per_shard = max_pooled;
+        self
+    }
+
+    /// Replace the full object-pool configuration.
+    #[must_use]
+    pub fn object_pools(mut self, config: crate::runtime::object_pool::ObjectPoolConfig) -> Self {
+        self.config.object_pools = config;
+        self
+    }
+
     /// Select a storage-temperature policy for runtime metadata and retained evidence.
     #[must_use]
     pub fn arena_temperature_policy(
@@ -2580,6 +2753,65 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Limit the runtime to `limit` live tasks.
+    ///
+    /// Fallible spawns past the limit fail with [`SpawnError::AtCapacity`];
+    /// [`RuntimeHandle::spawn`] blocks the spawning thread until a task
+    /// completes instead. Finalizers are exempt.
+    #[must_use]
+    pub fn max_live_tasks(mut self, limit: usize) -> Self {
+        self.config.spawn_capacity.max_live_tasks = Some(limit);
+        self
+    }
+
+    /// Raise spawn pressure once `watermark` tasks are live.
+    ///
+    /// Crossings are logged and retained on the runtime's
+    /// [`SpawnCapacityGate`](crate::runtime::SpawnCapacityGate), and lower
+    /// the headroom of any pressure signal set with
+    /// [`spawn_capacity`](Self::spawn_capacity).
+    #[must_use]
+    pub fn spawn_soft_watermark(mut self, watermark: usize) -> Self {
+        self.config.spawn_capacity.soft_watermark = Some(watermark);
+        self
+    }
+
+    /// Replace the whole live-task admission configuration.
+    #[must_use]
+    pub fn spawn_capacity(mut self, config: SpawnCapacityConfig) -> Self {
+        self.config.spawn_capacity = config;
+        self
+    }
+
+    /// Select how ready dispatches are shared between regions.
+    ///
+    /// Under [`RegionFairness::RegionFair`] each region receives ready-lane
+    /// dispatches in proportion to its weight, however many ready tasks it
+    /// has; weights can be changed at runtime with
+    /// [`RuntimeHandle::set_region_weight`].
+    #[must_use]
+    pub fn region_fairness(mut self, fairness: RegionFairness) -> Self {
+        self.config.region_fairness = fairness;
+        self
+    }
+
+    /// Set the weight of regions that have not been given one explicitly.
+    ///
+    /// Zero is treated as one.
+    #[must_use]
+    pub fn default_region_weight(mut self, weight: u32) -> Self {
+        self.config.default_region_weight = weight;
+        self
+    }
+
+    /// Take the region fairness mode and default region weight from
+    /// `resources`.
+    #[must_use]
+    pub fn region_fairness_from(self, resources: &crate::config::ResourceConfig) -> Self {
+        self.region_fairness(resources.region_fairness)
+            .default_region_weight(resources.default_region_weight)
+    }
+
     /// Register a callback to run when a worker thread starts.
     #[must_use]
     pub fn on_thread_start<F>(mut self, f: F) -> Self
@@ -2636,6 +2868,18 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Write a crash report before the process aborts on a panic.
+    ///
+    /// Only takes effect when the binary is built with `panic = "abort"`;
+    /// unwinding builds contain task panics instead (see
+    /// [`PanicStrategy`](crate::runtime::PanicStrategy)). The hook chains to
+    /// any previously installed panic hook.
+    #[must_use]
+    pub fn crash_report(mut self, config: CrashReportConfig) -> Self {
+        self.config.crash_report = Some(config);
+        self
+    }
+
     /// Configure deadline monitoring for this runtime.
     ///
     /// The provided closure can customize thresholds and warning handlers.
@@ -2711,6 +2955,15 @@ impl RuntimeBuilder {
         .map_err(|e| {
             Error::new(crate::error::ErrorKind::ConfigError).with_message(e.to_string())
         })?;
+        let chaos = crate::runtime::env_config::chaos_from_env(
+            &crate::runtime::env_config::SystemEnvReader::new(),
+        )
+        .map_err(|e| {
+            Error::new(crate::error::ErrorKind::ConfigError).with_message(e.to_string())
+        })?;
+        if chaos.is_some() {
+            self.chaos = chaos;
+        }
         Ok(self)
     }
 
@@ -2749,6 +3002,7 @@ impl RuntimeBuilder {
             timer_driver: None,
             entropy_source: None,
             host_services: default_runtime_host_services(),
+            chaos: None,
         })
     }
 
@@ -2787,9 +3041,30 @@ impl RuntimeBuilder {
             timer_driver: None,
             entropy_source: None,
             host_services: default_runtime_host_services(),
+            chaos: None,
         })
     }
 
+    /// Build a runtime that a host event loop drives instead of worker threads.
+    ///
+    /// The runtime gets exactly one worker, whatever
+    /// [`worker_threads`](Self::worker_threads) says, and that worker runs
+    /// only inside [`EmbeddedDriver::turn`]. The blocking pool and deadline
+    /// monitor keep their own threads. See [`crate::runtime::embedded`] for
+    /// the turn contract and the host conformance checklist.
+    #[allow(clippy::result_large_err)]
+    pub fn build_embedded(mut self) -> Result<EmbeddedDriver, Error> {
+        let services = Arc::new(EmbeddedHostServices::default());
+        self.config.worker_threads = 1;
+        self.host_services = Arc::clone(&services) as Arc<dyn RuntimeHostServices>;
+        let runtime = self.build()?;
+        let worker = services.take_worker().ok_or_else(|| {
+            Error::new(crate::error::ErrorKind::Internal)
+                .with_message("runtime init: embedded build produced no worker")
+        })?;
+        Ok(EmbeddedDriver::new(runtime, worker))
+    }
+
     /// Build a runtime from this configuration.
     #[allow(clippy::result_large_err)]
     pub fn build(self) -> Result<Runtime, Error> {
@@ -2801,6 +3076,7 @@ impl RuntimeBuilder {
             timer_driver,
             entropy_source,
             host_services,
+            chaos,
         } = self;
         #[cfg(target_arch = "wasm32")]
         let _ = platform_reactor;
@@ -2811,8 +3087,19 @@ impl RuntimeBuilder {
         // the fallback regime available for burn-in/debugging.
         #[cfg(not(target_arch = "wasm32"))]
         let reactor = if platform_reactor && reactor.is_none() && io_driver.is_none() {
-            match crate::runtime::reactor::create_reactor() {
-                Ok(reactor) => Some(reactor),
+            let preference = crate::runtime::reactor::ReactorBackendPreference::from_env()
+                .unwrap_or(config.reactor_backend);
+            match crate::runtime::reactor::select_reactor(preference) {
+                Ok(selection) => {
+                    crate::tracing_compat::info!(
+                        event = "runtime_reactor_backend_selected",
+                        backend = selection.backend.as_str(),
+                        preference = preference.as_str(),
+                        fallback = selection.fallback_reason.is_some(),
+                        "platform reactor backend selected"
+                    );
+                    Some(selection.reactor)
+                }
                 Err(err) => {
                     #[cfg(not(feature = "tracing-integration"))]
                     let _ = &err;
@@ -2851,6 +3138,9 @@ impl RuntimeBuilder {
                 ),
             );
         }
+        if let Some(chaos) = chaos {
+            crate::runtime::chaos::install(chaos);
+        }
         Runtime::with_config_and_platform(
             config,
             reactor,
@@ -2984,14 +3274,16 @@ impl RuntimeBuilder {
     /// Enable or disable automatic platform I/O reactor construction.
     ///
     /// Default builds construct the platform backend via
-    /// [`create_reactor`](crate::runtime::reactor::create_reactor) — epoll
+    /// [`select_reactor`](crate::runtime::reactor::select_reactor) — epoll
     /// (or io_uring with the `io-uring` feature) on Linux, kqueue on
     /// BSD/macOS, IOCP on Windows — and attach an `IoDriver` backed by it, so
     /// sockets get readiness wakeups instead of timer-paced re-polls
     /// (br-asupersync-1ajbtl). Passing `false` keeps the old fallback regime
-    /// available for narrow debugging and platform bring-up. If the default
-    /// reactor cannot be constructed, `build()` logs a warning and falls back
-    /// to timer-paced polling rather than failing runtime construction.
+    /// available for narrow debugging and platform bring-up. If the native
+    /// backend cannot be initialized on Unix, the portable `poll(2)` reactor
+    /// is used (see [`reactor_backend`](Self::reactor_backend)); if no
+    /// reactor can be constructed at all, `build()` logs a warning and falls
+    /// back to timer-paced polling rather than failing runtime construction.
     ///
     /// An explicit [`with_reactor`](Self::with_reactor) or
     /// [`with_io_driver`](Self::with_io_driver) takes precedence; this flag
@@ -3042,6 +3334,17 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Installs a native chaos profile when the runtime is built.
+    ///
+    /// The profile is process-wide and stays installed after the runtime is
+    /// dropped; see [`chaos`](crate::runtime::chaos). Without the `chaos`
+    /// feature it is logged and ignored.
+    #[must_use]
+    pub fn chaos(mut self, config: crate::runtime::chaos::NativeChaosConfig) -> Self {
+        self.chaos = Some(config);
+        self
+    }
+
     /// Selects the runtime backing-state shape (Unified vs Sharded).
     ///
     /// br-asupersync-8fuxnt: opting in to
@@ -3507,6 +3810,90 @@ impl Runtime {
         guard.draining_region_count_for_snapshot()
     }
 
+    /// Cancels the root region, waits up to `budget` for the runtime to
+    /// become quiescent, and returns a [`ShutdownReport`] describing the drain.
+    ///
+    /// The report is also emitted as the final `runtime shutdown report`
+    /// tracing event. If the budget runs out first, the report is marked
+    /// incomplete and unfinished regions and tasks are measured up to the
+    /// moment the budget expired. Worker threads are not joined here; dropping
+    /// the runtime still performs the usual teardown.
+    pub fn shutdown(&self, budget: Duration) -> ShutdownReport {
+        let lock = || {
+            self.inner
+                .state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        };
+        let (started_at, cancel_effects) = {
+            let mut guard = lock();
+            let started_at = guard.current_runtime_time();
+            guard.begin_shutdown_report(started_at, budget);
+            let reason = CancelReason::shutdown().with_timestamp(started_at);
+            let effects = guard.cancel_request(self.inner.root_region, &reason, None);
+            (started_at, effects)
+        };
+        let (tasks_to_cancel, wakes) = cancel_effects.into_parts();
+        for (task_id, priority) in tasks_to_cancel {
+            self.inner.scheduler.inject_cancel(task_id, priority);
+        }
+        wakes.dispatch();
+        {
+            let mut guard = lock();
+            let now = guard.current_runtime_time();
+            guard.note_shutdown_fanout_dispatched(now);
+        }
+
+        let budget_nanos = u64::try_from(budget.as_nanos()).unwrap_or(u64::MAX);
+        let deadline = started_at.saturating_add_nanos(budget_nanos);
+        let report = loop {
+            let mut guard = lock();
+            let incomplete_reason = if guard.is_quiescent() {
+                None
+            } else if guard.current_runtime_time() >= deadline {
+                Some(format!(
+                    "shutdown budget of {budget:?} exhausted with {} live tasks",
+                    guard.live_task_count()
+                ))
+            } else {
+                drop(guard);
+                std::thread::yield_now();
+                continue;
+            };
+            break guard
+                .take_shutdown_report(incomplete_reason)
+                .expect("shutdown recorder armed above");
+        };
+        report.emit();
+        report
+    }
+
+    /// Returns the task-record object pool's hit/miss/recycle counters.
+    #[must_use]
+    pub fn task_record_pool_stats(&self) -> crate::runtime::object_pool::ObjectPoolStats {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .task_record_pool_stats()
+    }
+
+    /// Returns per-reactor I/O metrics, one entry per configured reactor.
+    ///
+    /// Empty when the runtime has no I/O driver attached.
+    #[must_use]
+    pub fn reactor_metrics(&self) -> Vec<crate::runtime::io_driver::ReactorMetrics> {
+        let driver = self
+            .inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .io_driver_handle();
+        driver
+            .as_ref()
+            .map_or_else(Vec::new, IoDriverHandle::reactor_metrics)
+    }
+
     /// Returns this runtime's resource monitor for runtime-local pressure snapshots.
     #[must_use]
     pub fn resource_monitor(&self) -> Arc<ResourceMonitor> {
@@ -3577,6 +3964,21 @@ impl Runtime {
         self.inner.blocking_handle()
     }
 
+    /// Returns live-task admission metrics: live tasks, rejections, and time
+    /// spawners spent waiting for capacity.
+    #[must_use]
+    pub fn spawn_capacity_metrics(&self) -> SpawnCapacityMetrics {
+        self.inner.spawn_capacity().metrics()
+    }
+
+    /// Returns per-region virtual runtime accounting, ordered by region id.
+    ///
+    /// Empty unless the runtime was built with [`RegionFairness::RegionFair`].
+    #[must_use]
+    pub fn region_vruntimes(&self) -> Vec<RegionVruntime> {
+        self.inner.scheduler.region_vruntimes()
+    }
+
     /// Returns the approximate number of ready tasks in the shared global
     /// scheduler queue.
     ///
@@ -3648,20 +4050,69 @@ impl RuntimeHandle {
 
     /// Spawn a task from outside async context.
     ///
+    /// If the runtime is at its [`max_live_tasks`](RuntimeBuilder::max_live_tasks)
+    /// limit, blocks the calling thread until a task completes, queued FIFO
+    /// behind other waiting spawners; the future is never dropped.
+    ///
+    /// # Panics
+    ///
     /// Panics if the runtime is no longer available or if the root region
-    /// rejects admission. Use [`RuntimeHandle::try_spawn`] to handle those
-    /// failures explicitly.
+    /// rejects admission. A runtime worker thread is never blocked, since it
+    /// could not complete the task it would wait on, so calling this on a
+    /// worker while the runtime is at capacity panics as well. Code that may
+    /// run on a worker should use [`try_spawn`](Self::try_spawn), which
+    /// returns [`SpawnError::AtCapacity`], or await
+    /// [`spawn_when_available`](Self::spawn_when_available).
     pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
     where
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        self.try_spawn(future)
+        self.try_inner()
+            .and_then(|inner| inner.spawn_blocking_for_capacity(future))
             .expect("failed to create runtime task")
     }
 
+    /// Spawn a task once the runtime has live-task capacity for it.
+    ///
+    /// At the [`max_live_tasks`](RuntimeBuilder::max_live_tasks) limit this
+    /// awaits [`SpawnCapacityGate::wait`], queued FIFO behind other waiting
+    /// spawners, without blocking the thread. Dropping the returned future
+    /// cancels the wait: the spawner leaves the queue, hands any release it
+    /// was granted to the next waiter, and drops `future` unspawned.
+    ///
+    /// # Errors
+    ///
+    /// Returns runtime-availability and root-region admission errors, as
+    /// [`try_spawn`](Self::try_spawn) does.
+    pub async fn spawn_when_available<F>(
+        &self,
+        future: F,
+    ) -> Result<JoinHandle<F::Output>, SpawnError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let mut future = future;
+        loop {
+            let gate = match self.try_inner()?.spawn_if_capacity(future)? {
+                CapacitySpawn::Spawned(handle) => return Ok(handle),
+                CapacitySpawn::Full(returned, gate) => {
+                    future = returned;
+                    gate
+                }
+            };
+            gate.wait().await;
+        }
+    }
+
     /// Spawn a task from outside async context, returning runtime-availability
     /// or admission errors instead of panicking.
+    ///
+    /// A runtime at its live-task limit rejects with
+    /// [`SpawnError::AtCapacity`]; await
+    /// [`spawn_when_available`](Self::spawn_when_available) to wait for
+    /// capacity instead.
     pub fn try_spawn<F>(&self, future: F) -> Result<JoinHandle<F::Output>, SpawnError>
     where
         F: Future + Send + 'static,
@@ -3670,15 +4121,49 @@ impl RuntimeHandle {
         self.try_inner()?.spawn(future)
     }
 
+    /// Returns the gate enforcing the runtime's live-task limits, if the
+    /// runtime is still alive.
+    #[must_use]
+    pub fn spawn_capacity(&self) -> Option<Arc<SpawnCapacityGate>> {
+        Some(self.try_inner().ok()?.spawn_capacity())
+    }
+
+    /// Sets the scheduling weight of `region`.
+    ///
+    /// Under [`RegionFairness::RegionFair`] the region's share of ready-lane
+    /// dispatches is proportional to its weight. The change applies to every
+    /// dispatch after the call and is recorded in the trace. Zero is treated
+    /// as one. Returns false, changing nothing, if the runtime is gone or
+    /// task-fair.
+    pub fn set_region_weight(&self, region: crate::types::RegionId, weight: u32) -> bool {
+        self.try_inner()
+            .is_ok_and(|inner| inner.set_region_weight(region, weight))
+    }
+
+    /// Returns per-region virtual runtime accounting, ordered by region id.
+    ///
+    /// Empty if the runtime is gone or task-fair.
+    #[must_use]
+    pub fn region_vruntimes(&self) -> Vec<RegionVruntime> {
+        self.try_inner()
+            .map_or_else(|_| Vec::new(), |inner| inner.scheduler.region_vruntimes())
+    }
+
     /// Spawn a task with a [`Cx`](crate::cx::Cx) from outside async context.
     ///
     /// Creates a child Cx in the runtime's root region and passes it to the
     /// factory closure. The Cx participates in structured cancellation: it
     /// will observe cancellation when the runtime shuts down.
     ///
-    /// Panics if the runtime is no longer available or if the root region
-    /// rejects admission. Use [`RuntimeHandle::try_spawn_with_cx`] to handle
-    /// those failures explicitly.
+    /// At the [`max_live_tasks`](RuntimeBuilder::max_live_tasks) limit this
+    /// waits for capacity exactly as [`spawn`](Self::spawn) does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime is no longer available, if the root region
+    /// rejects admission, or if called on a runtime worker thread while the
+    /// runtime is at capacity. Use [`RuntimeHandle::try_spawn_with_cx`] to
+    /// handle those failures explicitly.
     ///
     /// # Example
     ///
@@ -3695,7 +4180,8 @@ impl RuntimeHandle {
         F: FnOnce(crate::cx::Cx) -> Fut + Send + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.try_spawn_with_cx(f)
+        self.try_inner()
+            .and_then(|inner| inner.spawn_with_cx_blocking_for_capacity(f))
             .expect("failed to spawn task with cx");
     }
 
@@ -3721,7 +4207,7 @@ impl RuntimeHandle {
         F: FnOnce(crate::cx::Cx) -> Fut + Send + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.try_inner()?.spawn_with_cx(f)
+        self.try_inner()?.spawn_with_cx(f, false).map(drop)
     }
 
     /// Spawns a blocking task on the blocking pool.
@@ -3764,6 +4250,42 @@ impl RuntimeHandle {
     pub fn blocking_handle(&self) -> Option<crate::runtime::blocking_pool::BlockingPoolHandle> {
         self.try_inner().ok()?.blocking_handle()
     }
+
+    /// Returns the runtime log level and sampling control, if observability
+    /// is configured and the runtime is still alive.
+    ///
+    /// Overrides applied through it take effect for subsequent entries from
+    /// every task without restarting the runtime.
+    #[must_use]
+    pub fn log_control(&self) -> Option<crate::observability::LogControl> {
+        let inner = self.try_inner().ok()?;
+        let collector = inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .log_collector()?;
+        Some(collector.control().clone())
+    }
+
+    /// Subscribes to the runtime's region lifecycle events, or returns `None`
+    /// if the runtime has shut down.
+    ///
+    /// The stream never back-pressures the runtime: a subscriber that falls
+    /// behind loses its oldest events and is told how many it missed. See
+    /// [`crate::observability::region_events`].
+    #[must_use]
+    pub fn subscribe_region_events(
+        &self,
+        filter: crate::observability::RegionEventFilter,
+    ) -> Option<crate::observability::RegionEventStream> {
+        let inner = self.try_inner().ok()?;
+        let stream = inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .subscribe_region_events(filter);
+        Some(stream)
+    }
 }
 
 /// A join handle returned by [`RuntimeHandle::spawn`].
@@ -3844,9 +4366,9 @@ impl<F: Future> Future for CatchUnwind<F> {
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut this = self.project();
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let result = crate::runtime::panic_strategy::catch_unwind(|| {
             this.inner.as_mut().poll(cx)
-        }));
+        });
         match result {
             Ok(Poll::Pending) => Poll::Pending,
             Ok(Poll::Ready(v)) => Poll::Ready(Ok(v)),
@@ -3924,7 +4446,7 @@ impl RuntimeInner {
                     trace_capacity,
                     config.metrics_provider.clone(),
                 );
-                state.set_io_driver(IoDriverHandle::new(reactor));
+                state.set_io_driver(Self::reactor_group(reactor, config.reactor_count));
                 state.set_timer_driver(TimerDriverHandle::with_wall_clock());
                 state.set_logical_clock_mode(LogicalClockMode::Hybrid);
                 state
@@ -3939,11 +4461,63 @@ impl RuntimeInner {
         if let Some(source) = entropy_source {
             runtime_state.set_entropy_source(source);
         }
+        if let Some(seed) = config.rng_seed {
+            let source = runtime_state.entropy_source();
+            runtime_state.set_entropy_source(Arc::new(SeededStreams::new(source, seed)));
+        }
+        runtime_state.set_capability_audit(config.capability_audit.clone());
+        runtime_state.set_spawn_capacity(config.spawn_capacity.clone());
         runtime_state.set_spawn_authorization_key(config.security.spawn_authorization_key.clone());
         runtime_state.set_read_biased_region_snapshot(config.enable_read_biased_region_snapshot);
+        runtime_state.configure_object_pools(&config.object_pools, config.worker_threads);
         runtime_state
     }
 
+    /// Builds the I/O driver for `primary` plus `reactor_count - 1` extra
+    /// platform reactors of the same backend family. Reactors that cannot be
+    /// created are logged and skipped; the runtime runs with however many came
+    /// up.
+    fn reactor_group(primary: Arc<dyn Reactor>, reactor_count: usize) -> IoDriverHandle {
+        use crate::runtime::reactor::{ReactorBackend, ReactorBackendPreference};
+
+        let preference = if primary.backend() == ReactorBackend::Poll {
+            ReactorBackendPreference::Poll
+        } else {
+            ReactorBackendPreference::Auto
+        };
+        let mut additional = Vec::with_capacity(reactor_count.saturating_sub(1));
+        for _ in 1..reactor_count {
+            match crate::runtime::reactor::select_reactor(preference) {
+                Ok(selection) => additional.push(selection.reactor),
+                Err(err) => {
+                    #[cfg(not(feature = "tracing-integration"))]
+                    let _ = &err;
+                    crate::tracing_compat::warn!(
+                        event = "runtime_builder_additional_reactor_unavailable",
+                        error = %err,
+                        requested = reactor_count,
+                        created = additional.len() + 1,
+                        "additional platform reactor unavailable; running with fewer reactors"
+                    );
+                    break;
+                }
+            }
+        }
+        match IoDriverHandle::with_reactors(Arc::clone(&primary), additional) {
+            Ok(driver) => driver,
+            Err(err) => {
+                #[cfg(not(feature = "tracing-integration"))]
+                let _ = &err;
+                crate::tracing_compat::warn!(
+                    event = "runtime_builder_reactor_thread_unavailable",
+                    error = %err,
+                    "reactor poll thread could not be spawned; running with a single reactor"
+                );
+                IoDriverHandle::new(primary)
+            }
+        }
+    }
+
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     fn initialize_root_region(
         config: &RuntimeConfig,
@@ -3958,6 +4532,7 @@ impl RuntimeInner {
         if let Some(mode) = config.logical_clock_mode.clone() {
             guard.set_logical_clock_mode(mode);
         }
+        guard.set_deadline_clamp_slack(config.deadline_clamp_slack);
         guard.set_cancel_attribution_config(config.cancel_attribution);
         guard.set_obligation_leak_response(config.obligation_leak_response);
         guard.set_leak_escalation(config.leak_escalation);
@@ -4002,6 +4577,12 @@ impl RuntimeInner {
             runtime_state,
         ));
         let root_region = Self::initialize_root_region(&config, &state);
+        if let Some(report) = config.crash_report.clone() {
+            crate::runtime::panic_strategy::install_crash_report_hook(
+                report,
+                Arc::downgrade(&state),
+            );
+        }
 
         let mut scheduler = ThreeLaneScheduler::new_with_options(
             config.worker_threads,
@@ -4022,6 +4603,23 @@ impl RuntimeInner {
         scheduler.set_adaptive_batch_profile(scheduler_adaptive_ready_batch_profile(
             config.adaptive_ready_batch,
         ));
+        scheduler.set_region_fairness(config.region_fairness, config.default_region_weight);
+        if config.region_fairness.is_region_fair() {
+            let guard = state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let now = guard.current_runtime_time();
+            guard.record_trace_event(|seq| {
+                crate::trace::TraceEvent::user_trace(
+                    seq,
+                    now,
+                    format!(
+                        "scheduler fairness: {} default_weight={}",
+                        config.region_fairness, config.default_region_weight
+                    ),
+                )
+            });
+        }
         if let Some(mapping) = config.worker_cohort_map.as_ref() {
             scheduler
                 .set_worker_cohort_map(&mapping.worker_to_cohort)
@@ -4117,7 +4715,10 @@ impl RuntimeInner {
             return None;
         }
         let options = crate::runtime::blocking_pool::BlockingPoolOptions {
-            idle_timeout: Duration::from_secs(10),
+            idle_timeout: config
+                .blocking
+                .keep_alive
+                .unwrap_or(Duration::from_secs(10)),
             thread_name_prefix: format!("{}-blocking", config.thread_name_prefix),
             on_thread_start: config.on_thread_start.clone(),
             on_thread_stop: config.on_thread_stop.clone(),
@@ -4126,13 +4727,33 @@ impl RuntimeInner {
                 .worker_cohort_map
                 .as_ref()
                 .map(crate::runtime::config::WorkerCohortMapping::cohort_count),
+            queue_capacity: config.blocking.queue_capacity,
+            queue_full_policy: config.blocking.queue_full_policy,
             ..Default::default()
         };
-        Some(crate::runtime::blocking_pool::BlockingPool::with_config(
+        let pool = crate::runtime::blocking_pool::BlockingPool::with_config(
             config.blocking.min_threads,
             config.blocking.max_threads,
             options,
-        ))
+        );
+        let Some(lane) = config.blocking.cpu_lane.as_ref() else {
+            return Some(pool);
+        };
+        let cpu_options = crate::runtime::blocking_pool::BlockingPoolOptions {
+            idle_timeout: lane.keep_alive.unwrap_or(Duration::from_secs(10)),
+            thread_name_prefix: format!("{}-cpu", config.thread_name_prefix),
+            on_thread_start: config.on_thread_start.clone(),
+            on_thread_stop: config.on_thread_stop.clone(),
+            queue_capacity: lane.queue_capacity,
+            queue_full_policy: lane.queue_full_policy,
+            ..Default::default()
+        };
+        let cpu = crate::runtime::blocking_pool::BlockingPool::with_config(
+            lane.min_threads,
+            lane.max_threads,
+            cpu_options,
+        );
+        Some(pool.with_cpu_lane(cpu))
     }
 
     fn spawn<F>(&self, future: F) -> Result<JoinHandle<F::Output>, SpawnError>
@@ -4140,15 +4761,7 @@ impl RuntimeInner {
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        let join_state = Arc::new(Mutex::new(JoinState::new()));
-        let join_state_for_task = Arc::clone(&join_state);
-
-        let wrapped = async move {
-            // Ensure panics in the spawned task don't take down a worker thread. If the join
-            // handle is awaited, we re-raise the original panic payload on the awaiter.
-            let result = CatchUnwind { inner: future }.await;
-            complete_task(&join_state_for_task, result);
-        };
+        let (join_state, wrapped) = join_wrapped(future);
 
         // Mailbox admission mode (br-asupersync-dx-core-api-v2-u1z5hn.1.3):
         // reserve the root region's pending-spawn credit, then enqueue the
@@ -4213,12 +4826,85 @@ impl RuntimeInner {
         Ok(JoinHandle::new(join_state))
     }
 
+    /// Spawns `future` if the runtime is below its live-task limit, and
+    /// otherwise hands it back with the gate to wait on.
+    fn spawn_if_capacity<F>(&self, future: F) -> Result<CapacitySpawn<F>, SpawnError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        if self.spawn_mailbox.is_some() {
+            // Admission happens later on a worker, so checking here can only
+            // get the request ahead of the limit; a denial resolves the join
+            // handle.
+            let gate = self.spawn_capacity();
+            if !gate.has_capacity() {
+                return Ok(CapacitySpawn::Full(future, gate));
+            }
+            return self.spawn(future).map(CapacitySpawn::Spawned);
+        }
+
+        let (task_id, join_state, spawn_effects) = {
+            let mut guard = self
+                .state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            // Checked under the lock that admits, so the future is only
+            // handed over once it fits.
+            if !guard.has_spawn_capacity() {
+                let gate = Arc::clone(guard.spawn_capacity());
+                return Ok(CapacitySpawn::Full(future, gate));
+            }
+            let (join_state, wrapped) = join_wrapped(future);
+            let (task_id, _handle, spawn_effects) = guard.create_task_with_deferred_spawn_effects(
+                self.root_region,
+                Budget::new(),
+                wrapped,
+            )?;
+            (task_id, join_state, spawn_effects)
+        };
+
+        self.scheduler.inject_ready(task_id, Budget::new().priority);
+        spawn_effects.dispatch();
+
+        Ok(CapacitySpawn::Spawned(JoinHandle::new(join_state)))
+    }
+
+    /// Spawns `future`, blocking the calling thread while the runtime is at
+    /// its live-task limit (see [`SpawnCapacityGate::block_until_available`]).
+    fn spawn_blocking_for_capacity<F>(
+        &self,
+        future: F,
+    ) -> Result<JoinHandle<F::Output>, SpawnError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let mut future = future;
+        loop {
+            match self.spawn_if_capacity(future)? {
+                CapacitySpawn::Spawned(handle) => return Ok(handle),
+                CapacitySpawn::Full(returned, gate) => {
+                    gate.block_until_available()?;
+                    future = returned;
+                }
+            }
+        }
+    }
+
     /// Spawn a task with a [`Cx`](crate::cx::Cx) passed to the factory closure.
     ///
     /// The Cx is created in the root region and linked to the runtime's
     /// cancellation tree, so it will observe cancellation when the runtime
-    /// shuts down.
-    fn spawn_with_cx<F, Fut>(&self, f: F) -> Result<(), SpawnError>
+    /// shuts down. With `hand_back_when_full`, a runtime at its live-task
+    /// limit hands `f` back with the gate to wait on instead of failing with
+    /// [`SpawnError::AtCapacity`].
+    #[allow(clippy::type_complexity)]
+    fn spawn_with_cx<F, Fut>(
+        &self,
+        f: F,
+        hand_back_when_full: bool,
+    ) -> Result<Option<(F, Arc<SpawnCapacityGate>)>, SpawnError>
     where
         F: FnOnce(crate::cx::Cx) -> Fut + Send + 'static,
         Fut: Future<Output = ()> + Send + 'static,
@@ -4231,6 +4917,10 @@ impl RuntimeInner {
                 .state
                 .lock()
                 .unwrap_or_else(std::sync::PoisonError::into_inner);
+            // Checked under the lock that admits, as in `spawn_if_capacity`.
+            if hand_back_when_full && !guard.has_spawn_capacity() {
+                return Ok(Some((f, Arc::clone(guard.spawn_capacity()))));
+            }
 
             let system_cx = guard.create_system_cx();
             let (task_id, _handle, cx, _result_tx, spawn_effects) = guard
@@ -4268,6 +4958,21 @@ impl RuntimeInner {
         self.scheduler.inject_ready(task_id, Budget::new().priority);
         spawn_effects.dispatch();
 
+        Ok(None)
+    }
+
+    /// Spawns a Cx task, blocking the calling thread while the runtime is at
+    /// its live-task limit, as [`Self::spawn_blocking_for_capacity`] does.
+    fn spawn_with_cx_blocking_for_capacity<F, Fut>(&self, f: F) -> Result<(), SpawnError>
+    where
+        F: FnOnce(crate::cx::Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut f = f;
+        while let Some((returned, gate)) = self.spawn_with_cx(f, true)? {
+            gate.block_until_available()?;
+            f = returned;
+        }
         Ok(())
     }
 
@@ -4277,6 +4982,34 @@ impl RuntimeInner {
             .as_ref()
             .map(crate::runtime::blocking_pool::BlockingPool::handle)
     }
+
+    fn spawn_capacity(&self) -> Arc<SpawnCapacityGate> {
+        let guard = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(guard.spawn_capacity())
+    }
+
+    fn set_region_weight(&self, region: crate::types::RegionId, weight: u32) -> bool {
+        let weight = weight.max(1);
+        if !self.scheduler.set_region_weight(region, weight) {
+            return false;
+        }
+        let guard = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = guard.current_runtime_time();
+        guard.record_trace_event(|seq| {
+            crate::trace::TraceEvent::user_trace(
+                seq,
+                now,
+                format!("region weight: region={region} weight={weight}"),
+            )
+        });
+        true
+    }
 }
 
 impl Drop for RuntimeInner {
@@ -4313,6 +5046,21 @@ impl Drop for RuntimeInner {
             let _ = handle.join();
         }
         drop(handles);
+        // Workers are gone, so nothing else recycles into the pools; release
+        // the pooled records now rather than whenever the last handle drops.
+        // Secondary reactor poll threads are joined here too, since I/O
+        // handles held elsewhere would otherwise keep them alive.
+        let io_driver = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            state.drain_object_pools();
+            state.io_driver_handle()
+        };
+        if let Some(driver) = io_driver {
+            driver.shutdown_reactors();
+        }
 
         if let (Some(liveness), Some(mailbox)) = (spawn_liveness, gateway_mailbox) {
             while liveness.strong_count() > 0 {
@@ -4335,6 +5083,14 @@ impl Drop for RuntimeInner {
     }
 }
 
+/// Outcome of [`RuntimeInner::spawn_if_capacity`].
+enum CapacitySpawn<F: Future> {
+    /// The task was admitted.
+    Spawned(JoinHandle<F::Output>),
+    /// The runtime is at its live-task limit; the future is handed back.
+    Full(F, Arc<SpawnCapacityGate>),
+}
+
 struct JoinState<T> {
     result: Option<std::thread::Result<T>>,
     waker: Option<Waker>,
@@ -4364,6 +5120,28 @@ fn complete_task<T>(state: &Arc<Mutex<JoinState<T>>>, output: std::thread::Resul
     }
 }
 
+/// Wraps `future` so its output, or its panic, completes a join state.
+fn join_wrapped<F>(
+    future: F,
+) -> (
+    Arc<Mutex<JoinState<F::Output>>>,
+    impl Future<Output = ()> + Send + 'static,
+)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let join_state = Arc::new(Mutex::new(JoinState::new()));
+    let join_state_for_task = Arc::clone(&join_state);
+    let wrapped = async move {
+        // Ensure panics in the spawned task don't take down a worker thread. If the join
+        // handle is awaited, we re-raise the original panic payload on the awaiter.
+        let result = CatchUnwind { inner: future }.await;
+        complete_task(&join_state_for_task, result);
+    };
+    (join_state, wrapped)
+}
+
 struct ThreadWaker {
     thread: std::thread::Thread,
     woken: std::sync::atomic::AtomicBool,
@@ -5850,6 +6628,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn runtime_builder_rng_seed_reproduces_task_streams() {
+        init_test_logging();
+
+        let draws = |seed: Option<u64>| {
+            let mut builder = RuntimeBuilder::current_thread();
+            if let Some(seed) = seed {
+                builder = builder.rng_seed(seed);
+            }
+            let runtime = builder.build().expect("runtime build");
+            runtime.block_on(runtime.handle().spawn(async {
+                let cx = Cx::current().expect("task context");
+                let rng = cx.rng_named("shard_assignment");
+                (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>()
+            }))
+        };
+
+        let first = draws(Some(77));
+        assert_eq!(first, draws(Some(77)), "a fixed seed replays task streams");
+        assert_ne!(first, draws(Some(78)), "the seed selects the streams");
+        assert_ne!(first, draws(None), "unseeded streams come from OS entropy");
+    }
+
     #[test]
     fn runtime_builder_platform_seams_propagate_into_task_contexts() {
         init_test_logging();
@@ -6765,6 +7566,97 @@ worker_threads = 16
         );
     }
 
+    /// Sockets pinned to one reactor of a multi-reactor runtime are served by
+    /// that reactor's thread, and dropping the runtime joins those threads.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn multiple_reactors_serve_pinned_sockets_and_join_on_drop() {
+        use crate::io::{AsyncReadExt, AsyncWriteExt};
+
+        init_test_logging();
+        let runtime = RuntimeBuilder::new()
+            .worker_threads(2)
+            .reactors(3)
+            .build()
+            .expect("runtime build");
+        assert_eq!(runtime.reactor_metrics().len(), 3);
+
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+        let server = runtime.handle().spawn(async move {
+            let socket = crate::net::TcpSocket::new_v4().expect("socket");
+            socket.set_reactor_affinity(Some(2)).expect("affinity");
+            socket
+                .bind("127.0.0.1:0".parse().expect("addr"))
+                .expect("bind");
+            let listener = socket.listen(16).expect("listen");
+            addr_tx
+                .send(listener.local_addr().expect("local addr"))
+                .expect("send addr");
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            assert_eq!(stream.reactor_affinity(), Some(2));
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.expect("server read");
+            stream.write_all(&buf).await.expect("server write");
+        });
+        let addr = addr_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("listener address");
+
+        // Connect only once the accept has parked on reactor 2.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while runtime.reactor_metrics()[2].registered_handles == 0 {
+            assert!(Instant::now() < deadline, "listener never registered");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let driver = runtime.block_on(runtime.handle().spawn(async move {
+            let socket = crate::net::TcpSocket::new_v4().expect("socket");
+            socket.set_reactor_affinity(Some(2)).expect("affinity");
+            let mut stream = socket.connect(addr).await.expect("connect");
+            stream.write_all(b"ping").await.expect("client write");
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.expect("client read");
+            assert_eq!(&buf, b"ping");
+            Cx::current()
+                .expect("spawned task has a Cx")
+                .io_driver_handle()
+                .expect("platform reactor attached")
+        }));
+        runtime.block_on(server);
+
+        let metrics = runtime.reactor_metrics();
+        assert!(
+            metrics[2].events_dispatched >= 1,
+            "pinned reactor dispatched the accept: {metrics:?}"
+        );
+        assert_eq!(
+            metrics[1].registered_handles, 0,
+            "nothing pinned to reactor 1: {metrics:?}"
+        );
+
+        drop(runtime);
+
+        // Drop joined the secondary reactor threads; later registrations
+        // fall back to the primary reactor.
+        let (sock, _peer) = std::os::unix::net::UnixStream::pair().expect("socket pair");
+        let registration = driver
+            .register_with_affinity(
+                &sock,
+                Interest::READABLE,
+                std::task::Waker::noop().clone(),
+                Some(2),
+            )
+            .expect("register after shutdown");
+        let registered: Vec<_> = driver
+            .reactor_metrics()
+            .iter()
+            .map(|metrics| metrics.registered_handles)
+            .collect();
+        assert_eq!(registered[0], 1, "post-shutdown placement: {registered:?}");
+        assert_eq!(registered[2], 0, "post-shutdown placement: {registered:?}");
+        drop(registration);
+    }
+
     #[test]
     fn current_handle_returns_none_during_thread_local_teardown() {
         init_test_logging();
@@ -7322,6 +8214,57 @@ worker_threads = 16
         );
     }
 
+    #[test]
+    fn initialize_runtime_state_applies_object_pool_config() {
+        init_test_logging();
+
+        let config = RuntimeConfig {
+            worker_threads: 3,
+            object_pools: crate::runtime::object_pool::ObjectPoolConfig {
+                max_pooled_per_shard: 17,
+                shards: 0,
+            },
+            ..RuntimeConfig::default()
+        };
+        let state = RuntimeInner::initialize_runtime_state(&config, None, None, None, None);
+
+        assert_eq!(state.tasks.task_record_pool_capacity(), 17);
+        assert!(state.tasks.task_record_pool_enabled());
+    }
+
+    #[test]
+    fn task_churn_reuses_pooled_records_and_drain_empties_pool() {
+        init_test_logging();
+
+        let runtime = RuntimeBuilder::current_thread()
+            .object_pool_max_per_shard(8)
+            .build()
+            .expect("runtime build");
+        for round in 0..64u32 {
+            let value = runtime.block_on(runtime.handle().spawn(async move { round }));
+            assert_eq!(value, round);
+        }
+
+        let stats = runtime.task_record_pool_stats();
+        assert!(stats.hits > 0, "churn should be served from the pool: {stats:?}");
+        assert!(stats.recycled > 0, "finished tasks should be recycled: {stats:?}");
+
+        let mut state = runtime
+            .inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert!(state.tasks.recycled_task_record_count() <= 8);
+        state.drain_object_pools();
+        assert_eq!(state.tasks.recycled_task_record_count(), 0);
+        let after = state.task_record_pool_stats();
+        assert_eq!(
+            after.drained,
+            after.recycled - after.hits,
+            "every pooled record is either reused or drained"
+        );
+    }
+
     #[test]
     fn initialize_runtime_state_applies_read_biased_region_snapshot_gate() {
         init_test_logging();
@@ -10,6 +10,7 @@
 //! - NUMA-topology-independent pressure calculations
 //! - Deterministic cache behavior for reproducible lab runs
 
+use crate::runtime::reclaim::{ReclaimFuture, Reclaimable};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -501,6 +502,18 @@ impl ArtifactCache {
     }
 }
 
+impl Reclaimable for parking_lot::Mutex<ArtifactCache> {
+    /// Evicts with the configured policy until `target_bytes` are freed.
+    fn reclaim(&self, target_bytes: u64) -> ReclaimFuture<'_> {
+        let mut cache = self.lock();
+        let before = cache.current_size_bytes;
+        cache.evict(target_bytes);
+        let freed = before.saturating_sub(cache.current_size_bytes);
+        drop(cache);
+        Box::pin(std::future::ready(freed))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,589 @@
+//! Randomized scheduling perturbation for the native runtime.
+//!
+//! The lab runtime explores interleavings deterministically, but only the ones
+//! its scenarios model. Chaos mode shakes the *native* runtime instead: while
+//! a profile is installed, workers occasionally yield their OS thread before
+//! polling a task, batches of simultaneously woken tasks are woken in shuffled
+//! order, and channel sends and mutex acquisitions are delayed by a small
+//! random amount. Races that depend on the usual ordering of these events
+//! surface far more often under real parallelism.
+//!
+//! # Enabling
+//!
+//! Perturbation code exists only with the `chaos` Cargo feature. Without it
+//! every hook in this module inlines to an empty body, [`install`] only logs
+//! that the request was ignored, and [`stats`] reads all-zero.
+//!
+//! With the feature on, chaos stays inactive until a profile is installed,
+//! either directly with [`install`], through
+//! [`RuntimeBuilder::chaos`](super::builder::RuntimeBuilder::chaos), or from
+//! the `ASUPERSYNC_CHAOS*` variables read by
+//! [`RuntimeBuilder::with_env_overrides`](super::builder::RuntimeBuilder::with_env_overrides).
+//!
+//! # Seeds
+//!
+//! Every decision draws from one process-wide generator seeded by the profile.
+//! The seed is logged when the profile is installed so a failing integration
+//! run can be correlated with its perturbation profile. Draws are shared by
+//! all worker threads, so the same seed produces a similar profile, not an
+//! identical one; exact replay remains the lab runtime's job.
+//!
+//! # Labels
+//!
+//! Each injection point has a label: [`POLL`], [`WAKE`], [`CHANNEL_SEND`],
+//! and [`MUTEX_LOCK`] for the runtime's own points, plus any label passed to
+//! [`perturb`] from application code. Labels listed with
+//! [`NativeChaosConfig::exclude`] are never perturbed.
+//!
+//! ```ignore
+//! use asupersync::runtime::chaos::{self, NativeChaosConfig};
+//!
+//! let runtime = RuntimeBuilder::new()
+//!     .chaos(
+//!         NativeChaosConfig::new(0xC0FFEE)
+//!             .with_yield_probability(0.05)
+//!             .exclude(chaos::MUTEX_LOCK),
+//!     )
+//!     .build()?;
+//! ```
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+#[cfg(feature = "chaos")]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(feature = "chaos")]
+use std::sync::{Arc, RwLock};
+
+/// Label of the forced yield a worker may take before polling a task.
+pub const POLL: &str = "poll";
+/// Label of the shuffle applied to a batch of wakers woken together.
+pub const WAKE: &str = "wake";
+/// Label of the delay injected into channel sends.
+pub const CHANNEL_SEND: &str = "channel.send";
+/// Label of the delay injected into async mutex acquisitions.
+pub const MUTEX_LOCK: &str = "mutex.lock";
+
+/// Perturbation intensity for the native runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeChaosConfig {
+    seed: Option<u64>,
+    yield_probability: f64,
+    wake_shuffle_probability: f64,
+    delay_probability: f64,
+    delay_range: RangeInclusive<Duration>,
+    excluded: Vec<String>,
+}
+
+impl Default for NativeChaosConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            yield_probability: 0.01,
+            wake_shuffle_probability: 0.1,
+            delay_probability: 0.01,
+            delay_range: Duration::from_micros(1)..=Duration::from_micros(100),
+            excluded: Vec::new(),
+        }
+    }
+}
+
+impl NativeChaosConfig {
+    /// Default intensity with a fixed seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..Self::default()
+        }
+    }
+
+    /// Default intensity with a seed drawn from OS entropy at install time.
+    #[must_use]
+    pub fn from_entropy() -> Self {
+        Self::default()
+    }
+
+    /// Probability that a worker yields its thread before a poll.
+    #[must_use]
+    pub fn with_yield_probability(mut self, probability: f64) -> Self {
+        self.yield_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Probability that a batch of wakers is woken in shuffled order.
+    #[must_use]
+    pub fn with_wake_shuffle_probability(mut self, probability: f64) -> Self {
+        self.wake_shuffle_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Probability that a delay point sleeps.
+    #[must_use]
+    pub fn with_delay_probability(mut self, probability: f64) -> Self {
+        self.delay_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Bounds of an injected delay, drawn uniformly.
+    #[must_use]
+    pub fn with_delay_range(mut self, range: RangeInclusive<Duration>) -> Self {
+        self.delay_range = range;
+        self
+    }
+
+    /// Never perturbs operations carrying `label`.
+    #[must_use]
+    pub fn exclude(mut self, label: impl Into<String>) -> Self {
+        self.excluded.push(label.into());
+        self
+    }
+
+    /// The fixed seed, if one was given.
+    #[must_use]
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns `true` if `label` is excluded from perturbation.
+    #[must_use]
+    pub fn is_excluded(&self, label: &str) -> bool {
+        self.excluded.iter().any(|excluded| excluded == label)
+    }
+}
+
+/// Cumulative injection counts since process start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// Forced thread yields taken before a poll.
+    pub yields: u64,
+    /// Waker batches woken in shuffled order.
+    pub wake_shuffles: u64,
+    /// Delays injected at delay points.
+    pub delays: u64,
+    /// Total injected delay, in nanoseconds.
+    pub delay_nanos: u64,
+    /// Injection points skipped because their label is excluded.
+    pub excluded: u64,
+}
+
+/// Returns `true` if perturbation code is compiled into this build.
+#[must_use]
+pub const fn compiled() -> bool {
+    cfg!(feature = "chaos")
+}
+
+/// Installs `config` process-wide and returns the seed in effect.
+///
+/// Replaces any previously installed profile. Without the `chaos` feature
+/// this only logs that the profile was ignored.
+pub fn install(config: NativeChaosConfig) -> u64 {
+    let seed = config
+        .seed
+        .unwrap_or_else(|| crate::util::EntropySource::next_u64(&crate::util::OsEntropy));
+    #[cfg(feature = "chaos")]
+    {
+        crate::tracing_compat::warn!(
+            seed = seed,
+            yield_probability = config.yield_probability,
+            wake_shuffle_probability = config.wake_shuffle_probability,
+            delay_probability = config.delay_probability,
+            "native chaos mode enabled"
+        );
+        imp::install(config, seed);
+    }
+    #[cfg(not(feature = "chaos"))]
+    {
+        let _ = config;
+        crate::tracing_compat::warn!(
+            seed = seed,
+            "native chaos mode requested but the `chaos` feature is not compiled in"
+        );
+    }
+    seed
+}
+
+/// Removes the installed profile, if any.
+#[inline]
+pub fn uninstall() {
+    #[cfg(feature = "chaos")]
+    imp::uninstall();
+}
+
+/// Seed of the installed profile, if chaos is active.
+#[must_use]
+pub fn active_seed() -> Option<u64> {
+    #[cfg(feature = "chaos")]
+    {
+        imp::active_seed()
+    }
+    #[cfg(not(feature = "chaos"))]
+    {
+        None
+    }
+}
+
+/// Returns the cumulative injection counts.
+#[must_use]
+pub fn stats() -> ChaosStats {
+    #[cfg(feature = "chaos")]
+    {
+        imp::stats()
+    }
+    #[cfg(not(feature = "chaos"))]
+    {
+        ChaosStats::default()
+    }
+}
+
+/// Delay point for application code: sleeps for a random delay with the
+/// installed profile's delay probability unless `label` is excluded.
+#[inline]
+pub fn perturb(label: &str) {
+    #[cfg(feature = "chaos")]
+    imp::delay(label);
+    #[cfg(not(feature = "chaos"))]
+    let _ = label;
+}
+
+/// Worker hook run before each task poll.
+#[inline]
+pub(crate) fn before_poll() {
+    #[cfg(feature = "chaos")]
+    imp::before_poll();
+}
+
+/// Shuffles a batch of wakers that are about to be woken together.
+#[inline]
+pub(crate) fn shuffle<T>(batch: &mut [T]) {
+    #[cfg(feature = "chaos")]
+    imp::shuffle(batch);
+    #[cfg(not(feature = "chaos"))]
+    let _ = batch;
+}
+
+#[cfg(feature = "chaos")]
+mod imp {
+    use super::{Arc, AtomicBool, AtomicU64, ChaosStats, NativeChaosConfig, Ordering, RwLock};
+    use super::{POLL, WAKE};
+    use std::time::Duration;
+
+    struct Profile {
+        config: NativeChaosConfig,
+        seed: u64,
+    }
+
+    static ENABLED: AtomicBool = AtomicBool::new(false);
+    static PROFILE: RwLock<Option<Arc<Profile>>> = RwLock::new(None);
+    static RNG: AtomicU64 = AtomicU64::new(0);
+
+    static YIELDS: AtomicU64 = AtomicU64::new(0);
+    static WAKE_SHUFFLES: AtomicU64 = AtomicU64::new(0);
+    static DELAYS: AtomicU64 = AtomicU64::new(0);
+    static DELAY_NANOS: AtomicU64 = AtomicU64::new(0);
+    static EXCLUDED: AtomicU64 = AtomicU64::new(0);
+
+    pub(super) fn install(config: NativeChaosConfig, seed: u64) {
+        let mut profile = PROFILE.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        RNG.store(seed, Ordering::Relaxed);
+        *profile = Some(Arc::new(Profile { config, seed }));
+        ENABLED.store(true, Ordering::Release);
+    }
+
+    pub(super) fn uninstall() {
+        let mut profile = PROFILE.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        ENABLED.store(false, Ordering::Release);
+        *profile = None;
+    }
+
+    pub(super) fn active_seed() -> Option<u64> {
+        active().map(|profile| profile.seed)
+    }
+
+    pub(super) fn stats() -> ChaosStats {
+        ChaosStats {
+            yields: YIELDS.load(Ordering::Relaxed),
+            wake_shuffles: WAKE_SHUFFLES.load(Ordering::Relaxed),
+            delays: DELAYS.load(Ordering::Relaxed),
+            delay_nanos: DELAY_NANOS.load(Ordering::Relaxed),
+            excluded: EXCLUDED.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(super) fn before_poll() {
+        let Some(profile) = admitted(POLL) else {
+            return;
+        };
+        if chance(profile.config.yield_probability) {
+            YIELDS.fetch_add(1, Ordering::Relaxed);
+            std::thread::yield_now();
+        }
+    }
+
+    pub(super) fn shuffle<T>(batch: &mut [T]) {
+        if batch.len() < 2 {
+            return;
+        }
+        let Some(profile) = admitted(WAKE) else {
+            return;
+        };
+        if !chance(profile.config.wake_shuffle_probability) {
+            return;
+        }
+        WAKE_SHUFFLES.fetch_add(1, Ordering::Relaxed);
+        for i in (1..batch.len()).rev() {
+            let j = (draw() % (i as u64 + 1)) as usize;
+            batch.swap(i, j);
+        }
+    }
+
+    pub(super) fn delay(label: &str) {
+        let Some(profile) = admitted(label) else {
+            return;
+        };
+        if !chance(profile.config.delay_probability) {
+            return;
+        }
+        let min = super::duration_nanos(*profile.config.delay_range.start());
+        let max = super::duration_nanos(*profile.config.delay_range.end()).max(min);
+        let span = max - min;
+        let nanos = if span == u64::MAX {
+            draw()
+        } else {
+            min + draw() % (span + 1)
+        };
+        DELAYS.fetch_add(1, Ordering::Relaxed);
+        DELAY_NANOS.fetch_add(nanos, Ordering::Relaxed);
+        std::thread::sleep(Duration::from_nanos(nanos));
+    }
+
+    fn active() -> Option<Arc<Profile>> {
+        if !ENABLED.load(Ordering::Acquire) {
+            return None;
+        }
+        PROFILE
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// The installed profile, unless it excludes `label`.
+    fn admitted(label: &str) -> Option<Arc<Profile>> {
+        let profile = active()?;
+        if profile.config.is_excluded(label) {
+            EXCLUDED.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(profile)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn chance(probability: f64) -> bool {
+        probability > 0.0 && ((draw() >> 11) as f64 / (1_u64 << 53) as f64) < probability
+    }
+
+    /// SplitMix64 over a shared counter: lock-free and seed-determined, with
+    /// the interleaving of draws across threads left to the scheduler.
+    fn draw() -> u64 {
+        let mut z = RNG
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(feature = "chaos")]
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Without the feature, hooks are empty and nothing is ever counted.
+    #[cfg(not(feature = "chaos"))]
+    #[test]
+    fn hooks_are_inert_when_feature_disabled() {
+        assert!(!compiled());
+        let seed = install(
+            NativeChaosConfig::new(7)
+                .with_yield_probability(1.0)
+                .with_wake_shuffle_probability(1.0)
+                .with_delay_probability(1.0),
+        );
+        assert_eq!(seed, 7);
+        let mut batch = [1, 2, 3, 4, 5, 6, 7, 8];
+        for _ in 0..64 {
+            before_poll();
+            shuffle(&mut batch);
+            perturb(CHANNEL_SEND);
+        }
+        assert_eq!(batch, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(active_seed(), None);
+        assert_eq!(stats(), ChaosStats::default());
+    }
+
+    #[test]
+    fn config_builders_clamp_and_record_exclusions() {
+        let config = NativeChaosConfig::new(3)
+            .with_yield_probability(2.0)
+            .with_delay_probability(-1.0)
+            .exclude(MUTEX_LOCK)
+            .exclude("app.flush");
+        assert_eq!(config.seed(), Some(3));
+        assert!((config.yield_probability - 1.0).abs() < f64::EPSILON);
+        assert!(config.delay_probability.abs() < f64::EPSILON);
+        assert!(config.is_excluded(MUTEX_LOCK));
+        assert!(config.is_excluded("app.flush"));
+        assert!(!config.is_excluded(CHANNEL_SEND));
+        assert_eq!(NativeChaosConfig::from_entropy().seed(), None);
+    }
+
+    /// The profile is process-global, so feature-on tests serialize on this
+    /// lock and assert on deltas. Run the `chaos` lane on its own: runtimes in
+    /// concurrently running tests would draw from the installed profile too.
+    #[cfg(feature = "chaos")]
+    static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[cfg(feature = "chaos")]
+    fn with_profile<R>(config: NativeChaosConfig, f: impl FnOnce() -> R) -> R {
+        let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        install(config);
+        let result = f();
+        uninstall();
+        result
+    }
+
+    #[cfg(feature = "chaos")]
+    fn quiet(seed: u64) -> NativeChaosConfig {
+        NativeChaosConfig::new(seed)
+            .with_yield_probability(0.0)
+            .with_wake_shuffle_probability(0.0)
+            .with_delay_probability(0.0)
+            .with_delay_range(Duration::ZERO..=Duration::ZERO)
+    }
+
+    #[cfg(feature = "chaos")]
+    #[test]
+    fn yield_probability_scales_injection_count() {
+        let yields_at = |probability: f64| {
+            with_profile(quiet(11).with_yield_probability(probability), || {
+                let before = stats().yields;
+                for _ in 0..4_000 {
+                    before_poll();
+                }
+                stats().yields - before
+            })
+        };
+        let none = yields_at(0.0);
+        let low = yields_at(0.05);
+        let high = yields_at(0.5);
+        let all = yields_at(1.0);
+        assert_eq!(none, 0);
+        assert!((100..=300).contains(&low), "~200 expected at 5%, got {low}");
+        assert!((1_700..=2_300).contains(&high), "~2000 expected at 50%, got {high}");
+        assert!(all >= 4_000);
+    }
+
+    #[cfg(feature = "chaos")]
+    #[test]
+    fn delay_bounds_are_respected() {
+        let (delays, nanos) = with_profile(
+            quiet(5)
+                .with_delay_probability(1.0)
+                .with_delay_range(Duration::from_nanos(100)..=Duration::from_nanos(200)),
+            || {
+                let before = stats();
+                for _ in 0..50 {
+                    perturb(CHANNEL_SEND);
+                }
+                let after = stats();
+                (after.delays - before.delays, after.delay_nanos - before.delay_nanos)
+            },
+        );
+        assert!(delays >= 50);
+        assert!(nanos >= 50 * 100, "every delay at least the lower bound");
+    }
+
+    #[cfg(feature = "chaos")]
+    #[test]
+    fn excluded_labels_are_never_perturbed() {
+        let (stats_delta, batch) = with_profile(
+            quiet(9)
+                .with_delay_probability(1.0)
+                .with_wake_shuffle_probability(1.0)
+                .exclude("app.commit")
+                .exclude(WAKE),
+            || {
+                let before = stats();
+                let mut batch: Vec<u32> = (0..32).collect();
+                for _ in 0..20 {
+                    perturb("app.commit");
+                    shuffle(&mut batch);
+                }
+                let after = stats();
+                ((after.delays - before.delays, after.excluded - before.excluded), batch)
+            },
+        );
+        let (delays, excluded) = stats_delta;
+        assert_eq!(delays, 0, "excluded delay point never slept");
+        assert!(excluded >= 40);
+        assert_eq!(batch, (0..32).collect::<Vec<_>>(), "excluded wake never shuffled");
+    }
+
+    /// A lost-update race: each task reads a shared counter, awaits a yield,
+    /// then writes back. Chaos yields before polls widen the window between
+    /// the read and the write.
+    #[cfg(feature = "chaos")]
+    fn racy_fixture_loses_updates() -> bool {
+        use crate::runtime::{RuntimeBuilder, yield_now};
+
+        let runtime = RuntimeBuilder::new()
+            .worker_threads(4)
+            .build()
+            .expect("runtime");
+        let counter = Arc::new(AtomicU64::new(0));
+        runtime.block_on(async {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    let counter = Arc::clone(&counter);
+                    runtime.handle().spawn(async move {
+                        let seen = counter.load(Ordering::SeqCst);
+                        yield_now().await;
+                        counter.store(seen + 1, Ordering::SeqCst);
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.await;
+            }
+        });
+        counter.load(Ordering::SeqCst) != 8
+    }
+
+    /// Statistical: run with `--features chaos -- --ignored`.
+    #[cfg(feature = "chaos")]
+    #[test]
+    #[ignore = "statistical; compares failure rates over many native runs"]
+    fn chaos_raises_racy_fixture_failure_rate() {
+        const RUNS: usize = 200;
+        let baseline = {
+            let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            uninstall();
+            (0..RUNS).filter(|_| racy_fixture_loses_updates()).count()
+        };
+        let chaotic = with_profile(
+            NativeChaosConfig::new(0xC4A0)
+                .with_yield_probability(0.3)
+                .with_delay_probability(0.3),
+            || (0..RUNS).filter(|_| racy_fixture_loses_updates()).count(),
+        );
+        assert!(
+            chaotic > baseline * 2 + 5,
+            "chaos failures {chaotic}/{RUNS} vs baseline {baseline}/{RUNS}"
+        );
+    }
+}
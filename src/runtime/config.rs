@@ -14,15 +14,21 @@
 //! | `thread_name_prefix` | `"asupersync-worker"` |
 //! | `global_queue_limit` | 0 (unbounded) |
 //! | `steal_batch_size` | 16 |
+//! | `reactor_count` | 1 |
+//! | `reactor_backend` | `ReactorBackendPreference::Auto` |
 //! | `enable_parking` | true |
 //! | `poll_budget` | 128 |
 //! | `capacity_hints` | `None` (auto from `worker_threads`) |
+//! | `object_pools` | 256 pooled objects per shard, one shard per worker |
 //! | `arena_temperature_policy` | `ArenaTemperaturePolicy::Unified` |
 //! | `trace_storage_profile` | `TraceStorageProfile::Default` |
 //! | `browser_ready_handoff_limit` | 0 (disabled) |
 //! | `browser_worker_offload` | disabled, min cost 1024, max in-flight 16 |
 //! | `root_region_limits` | `None` |
+//! | `spawn_capacity` | unlimited, no watermark |
+//! | `region_fairness` | `RegionFairness::TaskFair`, default weight 1024 |
 //! | `observability` | `None` |
+//! | `crash_report` | `None` |
 //! | `enable_governor` | `false` |
 //! | `governor_interval` | `32` |
 //! | `enable_read_biased_region_snapshot` | `false` |
@@ -35,6 +41,9 @@ use crate::observability::metrics::{MetricsProvider, NoOpMetrics};
 use crate::record::{ObligationRecord, RegionLimits, RegionRecord, TaskRecord};
 use crate::runtime::TaskTable;
 use crate::runtime::deadline_monitor::{DeadlineWarning, MonitorConfig};
+use crate::runtime::object_pool::ObjectPoolConfig;
+use crate::runtime::panic_strategy::CrashReportConfig;
+use crate::runtime::reactor::ReactorBackendPreference;
 use crate::trace::distributed::LogicalClockMode;
 use crate::types::CancelAttributionConfig;
 use crate::util::Arena;
@@ -48,6 +57,7 @@ use crate::security::key::AuthKey;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Configuration for the blocking pool.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -82,7 +92,60 @@ impl BlockingPoolAffinityProfile {
     }
 }
 
+/// What a bounded blocking lane does when its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockingQueueFullPolicy {
+    /// The submitting task yields until the lane has queue capacity again.
+    #[default]
+    Wait,
+    /// The submission fails immediately with a typed queue-full error.
+    Reject,
+}
+
+/// Sizing for a dedicated blocking lane.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockingLaneConfig {
+    /// Minimum number of threads kept alive for this lane.
+    pub min_threads: usize,
+    /// Maximum number of threads for this lane. Zero disables the lane.
+    pub max_threads: usize,
+    /// Maximum number of queued (not yet running) tasks; `None` is unbounded.
+    pub queue_capacity: Option<usize>,
+    /// Behavior when `queue_capacity` is reached.
+    pub queue_full_policy: BlockingQueueFullPolicy,
+    /// Idle time before threads above `min_threads` are reaped; `None` uses
+    /// the pool default.
+    pub keep_alive: Option<Duration>,
+}
+
+impl BlockingLaneConfig {
+    /// Creates a lane configuration with the given thread bounds.
+    #[must_use]
+    pub fn new(min_threads: usize, max_threads: usize) -> Self {
+        Self {
+            min_threads,
+            max_threads,
+            ..Self::default()
+        }
+    }
+
+    /// Normalize configuration values to safe defaults.
+    pub fn normalize(&mut self) {
+        if self.max_threads < self.min_threads {
+            self.max_threads = self.min_threads;
+        }
+        if self.queue_capacity == Some(0) {
+            self.queue_capacity = Some(1);
+        }
+    }
+}
+
 /// Configuration for the blocking pool.
+///
+/// The top-level thread, queue, and keep-alive settings size the default
+/// [`BlockingKind::Io`](crate::runtime::BlockingKind::Io) lane. Setting
+/// `cpu_lane` gives CPU-bound work its own threads and queue so it cannot
+/// starve short blocking I/O calls.
 #[derive(Clone, Default)]
 pub struct BlockingPoolConfig {
     /// Minimum number of blocking threads.
@@ -91,6 +154,15 @@ pub struct BlockingPoolConfig {
     pub max_threads: usize,
     /// Optional cohort-aware affinity profile for blocking work.
     pub affinity_profile: BlockingPoolAffinityProfile,
+    /// Maximum number of queued I/O-lane tasks; `None` is unbounded.
+    pub queue_capacity: Option<usize>,
+    /// Behavior when the I/O lane queue is full.
+    pub queue_full_policy: BlockingQueueFullPolicy,
+    /// Idle time before I/O-lane threads above `min_threads` are reaped;
+    /// `None` uses the pool default.
+    pub keep_alive: Option<Duration>,
+    /// Optional dedicated lane for CPU-bound blocking work.
+    pub cpu_lane: Option<BlockingLaneConfig>,
 }
 
 impl BlockingPoolConfig {
@@ -99,7 +171,20 @@ impl BlockingPoolConfig {
         if self.max_threads < self.min_threads {
             self.max_threads = self.min_threads;
         }
+        if self.queue_capacity == Some(0) {
+            self.queue_capacity = Some(1);
+        }
         self.affinity_profile.normalize();
+        if let Some(lane) = self.cpu_lane.as_mut() {
+            lane.normalize();
+        }
+        if self
+            .cpu_lane
+            .as_ref()
+            .is_some_and(|lane| lane.max_threads == 0)
+        {
+            self.cpu_lane = None;
+        }
     }
 }
 
@@ -1929,6 +2014,17 @@ pub struct RuntimeConfig {
     pub adaptive_ready_batch: AdaptiveReadyBatchConfig,
     /// Blocking pool configuration.
     pub blocking: BlockingPoolConfig,
+    /// Number of platform I/O reactors (default: 1).
+    ///
+    /// The primary reactor is polled by the workers; each additional one gets
+    /// a dedicated poll thread. Registrations are spread round-robin unless
+    /// pinned with an explicit reactor affinity.
+    pub reactor_count: usize,
+    /// Which platform reactor backend to create (default: native, falling
+    /// back to `poll(2)` if it fails to initialize).
+    ///
+    /// `ASUPERSYNC_REACTOR_BACKEND` overrides this at build time.
+    pub reactor_backend: ReactorBackendPreference,
     /// Enable parking for idle workers.
     pub enable_parking: bool,
     /// Time slice for cooperative yielding (polls).
@@ -1938,6 +2034,9 @@ pub struct RuntimeConfig {
     /// When `None`, capacities auto-scale from `worker_threads` using the
     /// historical 4-worker baseline (512 tasks / 128 regions / 256 obligations).
     pub capacity_hints: Option<RuntimeCapacityHints>,
+    /// Sizing for the per-worker object pools that recycle hot runtime
+    /// structures such as task records.
+    pub object_pools: ObjectPoolConfig,
     /// Storage-temperature policy for hot runtime metadata and retained evidence.
     pub arena_temperature_policy: ArenaTemperaturePolicy,
     /// Trace and diagnostic retention policy for the runtime.
@@ -1959,8 +2058,29 @@ pub struct RuntimeConfig {
     /// - No reactor: Lamport (deterministic lab-friendly)
     /// - With reactor: Hybrid (wall-clock + logical)
     pub logical_clock_mode: Option<LogicalClockMode>,
+    /// How far a `timeout()` may ask for more time than the ambient deadline
+    /// allows before its clamp is logged as a warning.
+    pub deadline_clamp_slack: Duration,
+    /// Fixed seed for the task random streams behind
+    /// [`Cx::rng`](crate::cx::Cx::rng), for reproducible production debugging.
+    ///
+    /// `None` (the default) seeds each task's streams from OS entropy. The
+    /// seed does not affect [`Cx::random_u64`](crate::cx::Cx::random_u64) or
+    /// other raw entropy draws.
+    pub rng_seed: Option<u64>,
+    /// Capability-usage audit shared by every task context, checking uses of
+    /// attenuable capabilities against a declared manifest (see
+    /// [`CapabilityAudit`](crate::cx::CapabilityAudit)).
+    pub capability_audit: Option<Arc<crate::cx::CapabilityAudit>>,
     /// Admission limits applied to the root region (if set).
     pub root_region_limits: Option<RegionLimits>,
+    /// Runtime-wide live-task limit and spawn-pressure watermark (see
+    /// [`SpawnCapacityGate`](crate::runtime::SpawnCapacityGate)).
+    pub spawn_capacity: crate::runtime::spawn_capacity::SpawnCapacityConfig,
+    /// How the scheduler shares ready dispatches between regions.
+    pub region_fairness: crate::runtime::scheduler::RegionFairness,
+    /// Weight of regions without an explicit one under region-fair scheduling.
+    pub default_region_weight: u32,
     /// Callback executed when a worker thread starts.
     pub on_thread_start: Option<Arc<dyn Fn() + Send + Sync>>,
     /// Callback executed when a worker thread stops.
@@ -1973,6 +2093,10 @@ pub struct RuntimeConfig {
     pub metrics_provider: Arc<dyn MetricsProvider>,
     /// Optional runtime observability configuration.
     pub observability: Option<ObservabilityConfig>,
+    /// Crash report written just before a `panic = "abort"` process dies.
+    ///
+    /// Ignored in unwinding builds, where panics are contained per task.
+    pub crash_report: Option<CrashReportConfig>,
     /// Limits for cancellation attribution cause chains.
     ///
     /// Used to bound memory growth when cancellation cascades across deep
@@ -2036,6 +2160,12 @@ impl RuntimeConfig {
         if self.steal_batch_size == 0 {
             self.steal_batch_size = 1;
         }
+        if self.default_region_weight == 0 {
+            self.default_region_weight = 1;
+        }
+        if self.reactor_count == 0 {
+            self.reactor_count = 1;
+        }
         self.adaptive_ready_batch.normalize(self.steal_batch_size);
         if self.poll_budget == 0 {
             self.poll_budget = 1;
@@ -2200,22 +2330,32 @@ impl Default for RuntimeConfig {
             steal_batch_size: 16,
             adaptive_ready_batch: AdaptiveReadyBatchConfig::default(),
             blocking: BlockingPoolConfig::default(),
+            reactor_count: 1,
+            reactor_backend: ReactorBackendPreference::Auto,
             enable_parking: true,
             poll_budget: 128,
             capacity_hints: None,
+            object_pools: ObjectPoolConfig::default(),
             arena_temperature_policy: ArenaTemperaturePolicy::Unified,
             trace_storage_profile: TraceStorageProfile::Default,
             browser_ready_handoff_limit: 0,
             browser_worker_offload: BrowserWorkerOffloadConfig::default(),
             cancel_lane_max_streak: 16,
             logical_clock_mode: None,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+            rng_seed: None,
+            capability_audit: None,
             root_region_limits: None,
+            spawn_capacity: crate::runtime::spawn_capacity::SpawnCapacityConfig::default(),
+            region_fairness: crate::runtime::scheduler::RegionFairness::TaskFair,
+            default_region_weight: crate::runtime::scheduler::DEFAULT_REGION_WEIGHT,
             on_thread_start: None,
             on_thread_stop: None,
             deadline_monitor: None,
             deadline_warning_handler: None,
             metrics_provider: Arc::new(NoOpMetrics),
             observability: None,
+            crash_report: None,
             cancel_attribution: CancelAttributionConfig::default(),
             // Plan v4 §I2 makes "no obligation leaks" a non-negotiable invariant;
             // the runtime fails fast (Panic) on detection by default. Tests and
@@ -9458,6 +9598,7 @@ mod tests {
                 min_threads: 4,
                 max_threads: 1,
                 affinity_profile: BlockingPoolAffinityProfile::Disabled,
+                ..BlockingPoolConfig::default()
             },
             enable_parking: true,
             poll_budget: 0,
@@ -9475,16 +9616,23 @@ mod tests {
             },
             cancel_lane_max_streak: 0,
             root_region_limits: None,
+            spawn_capacity: crate::runtime::spawn_capacity::SpawnCapacityConfig::default(),
+            region_fairness: crate::runtime::scheduler::RegionFairness::TaskFair,
+            default_region_weight: crate::runtime::scheduler::DEFAULT_REGION_WEIGHT,
             on_thread_start: None,
             on_thread_stop: None,
             deadline_monitor: None,
             deadline_warning_handler: None,
             metrics_provider: Arc::new(NoOpMetrics),
             observability: None,
+            crash_report: None,
             cancel_attribution: CancelAttributionConfig::new(1, 256),
             obligation_leak_response: ObligationLeakResponse::Log,
             leak_escalation: None,
             logical_clock_mode: None,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+            rng_seed: None,
+            capability_audit: None,
             enable_governor: false,
             governor_interval: 0,
             enable_read_biased_region_snapshot: false,
@@ -9623,6 +9771,7 @@ mod tests {
                 local_queue_soft_limit: 0,
                 spill_check_interval: 0,
             },
+            ..BlockingPoolConfig::default()
         };
         blocking.normalize();
         crate::assert_with_log!(
@@ -9761,6 +9910,7 @@ mod tests {
                 min_threads: 2,
                 max_threads: 4,
                 affinity_profile: BlockingPoolAffinityProfile::Disabled,
+                ..BlockingPoolConfig::default()
             },
             enable_parking: false,
             poll_budget: 32,
@@ -9778,16 +9928,23 @@ mod tests {
             },
             cancel_lane_max_streak: 16,
             root_region_limits: None,
+            spawn_capacity: crate::runtime::spawn_capacity::SpawnCapacityConfig::default(),
+            region_fairness: crate::runtime::scheduler::RegionFairness::TaskFair,
+            default_region_weight: crate::runtime::scheduler::DEFAULT_REGION_WEIGHT,
             on_thread_start: None,
             on_thread_stop: None,
             deadline_monitor: None,
             deadline_warning_handler: None,
             metrics_provider: Arc::new(NoOpMetrics),
             observability: None,
+            crash_report: None,
             cancel_attribution: CancelAttributionConfig::new(8, 1024),
             obligation_leak_response: ObligationLeakResponse::Silent,
             leak_escalation: None,
             logical_clock_mode: None,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+            rng_seed: None,
+            capability_audit: None,
             enable_governor: false,
             governor_interval: 7,
             enable_read_biased_region_snapshot: true,
@@ -10030,6 +10187,7 @@ mod tests {
                 local_queue_soft_limit: 16,
                 spill_check_interval: 4,
             },
+            ..BlockingPoolConfig::default()
         };
         let cloned = bp.clone();
         assert_eq!(cloned.min_threads, 2);
@@ -10037,6 +10195,30 @@ mod tests {
         assert_eq!(cloned.affinity_profile, bp.affinity_profile);
     }
 
+    #[test]
+    fn blocking_pool_config_normalizes_lanes() {
+        let mut bp = BlockingPoolConfig {
+            queue_capacity: Some(0),
+            cpu_lane: Some(BlockingLaneConfig {
+                queue_capacity: Some(0),
+                ..BlockingLaneConfig::new(3, 1)
+            }),
+            ..BlockingPoolConfig::default()
+        };
+        bp.normalize();
+        assert_eq!(bp.queue_capacity, Some(1));
+        let lane = bp.cpu_lane.as_ref().expect("lane with threads is kept");
+        assert_eq!(lane.max_threads, 3);
+        assert_eq!(lane.queue_capacity, Some(1));
+
+        let mut disabled = BlockingPoolConfig {
+            cpu_lane: Some(BlockingLaneConfig::new(0, 0)),
+            ..BlockingPoolConfig::default()
+        };
+        disabled.normalize();
+        assert!(disabled.cpu_lane.is_none(), "zero-thread lane is dropped");
+    }
+
     #[test]
     fn runtime_config_clone() {
         let config = RuntimeConfig::default();
@@ -0,0 +1,706 @@
+//! Turn-based driver for hosts that own the main loop.
+//!
+//! GUI toolkits, game engines, and existing reactors already run a loop on
+//! the thread that must also poll asupersync's tasks. Instead of handing that
+//! thread to [`Runtime::block_on`] or spawning worker threads,
+//! [`RuntimeBuilder::build_embedded`] returns an [`EmbeddedDriver`] whose
+//! single worker runs only inside [`EmbeddedDriver::turn`]. One turn:
+//!
+//! 1. clears the [`WakeSignal`] and consumes the worker's unpark permit,
+//! 2. polls I/O readiness once without blocking,
+//! 3. fires due timers and dispatches cancel, timed, and ready work in the
+//!    usual lane order until nothing is runnable or the [`TurnBudget`] runs
+//!    out,
+//! 4. reports a [`TurnResult`]: whether work is still pending and the next
+//!    deadline the host should wake for.
+//!
+//! Regions, cancellation, obligations, and task handles behave exactly as on
+//! a threaded runtime: only the thread that polls the tasks changes. Cross-
+//! thread spawns and wakeups raise the wake signal, so the host can sleep in
+//! its own primitive and still learn about new work. I/O readiness is only
+//! observed at the start of a turn, so a host with socket-heavy workloads
+//! should bound its sleep as well.
+//!
+//! [`LabRuntime::turn`](crate::lab::LabRuntime::turn) runs the same contract
+//! under virtual time, so a host loop can be exercised deterministically
+//! before it meets a real clock.
+//!
+//! # Conformance checklist
+//!
+//! A host integration is correct when it:
+//!
+//! - registers [`WakeSignal::on_wake`] or polls [`WakeSignal::pollable`]
+//!   before the first turn, and only schedules a turn from the callback,
+//! - runs a turn when the wake signal fired, when [`TurnResult::wait_hint`]
+//!   elapsed, or right away when the previous turn reported `more_work`,
+//! - never sleeps longer than [`TurnResult::wait_hint`] when it is `Some`,
+//! - calls `turn` from one thread and never from inside a task, a wake
+//!   callback, or another turn (see [Reentrancy](#reentrancy)),
+//! - sizes [`TurnBudget`] to its frame or tick deadline and treats
+//!   `budget_exhausted` as "yield to the host, then turn again",
+//! - keeps turning until [`TurnResult::quiescent`] when it wants tasks to
+//!   finish before dropping the driver; dropping it drops live tasks just
+//!   like dropping a threaded runtime.
+//!
+//! # Reentrancy
+//!
+//! A turn polls task futures on the host thread, so a task that calls back
+//! into the host can reach the host loop while the turn is still on the
+//! stack. Turns do not nest: [`EmbeddedDriver::turn`] returns
+//! [`TurnError::Reentrant`] if any embedded turn is already running on the
+//! calling thread, and leaves the runtime untouched. Blocking entry points
+//! such as [`Runtime::block_on`] and [`Runtime::shutdown`] wait for a worker
+//! to make progress, and the embedded worker only runs inside `turn`; call
+//! them from other threads only, while the host keeps turning.
+//!
+//! [`RuntimeBuilder::build_embedded`]: crate::runtime::RuntimeBuilder::build_embedded
+
+use crate::runtime::builder::ScopedRuntimeHandle;
+use crate::runtime::scheduler::ThreeLaneWorker;
+use crate::runtime::{Runtime, RuntimeHandle};
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::cell::Cell;
+use std::fmt;
+#[cfg(unix)]
+use std::io::{self, Read, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Default number of task polls allowed in one turn.
+pub const DEFAULT_TURN_POLLS: usize = 256;
+
+thread_local! {
+    static IN_TURN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the calling thread as inside a turn for the guard's lifetime.
+struct TurnGuard;
+
+impl TurnGuard {
+    fn enter() -> Result<Self, TurnError> {
+        if IN_TURN.with(|in_turn| in_turn.replace(true)) {
+            return Err(TurnError::Reentrant);
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for TurnGuard {
+    fn drop(&mut self) {
+        IN_TURN.with(|in_turn| in_turn.set(false));
+    }
+}
+
+/// Upper bound on the work one turn may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnBudget {
+    max_polls: usize,
+    max_time: Option<Duration>,
+}
+
+impl TurnBudget {
+    /// A budget of at most `max_polls` task polls and no time limit.
+    #[must_use]
+    pub const fn polls(max_polls: usize) -> Self {
+        Self {
+            max_polls,
+            max_time: None,
+        }
+    }
+
+    /// A budget of [`DEFAULT_TURN_POLLS`] polls and at most `limit` of time.
+    #[must_use]
+    pub const fn time(limit: Duration) -> Self {
+        Self::polls(DEFAULT_TURN_POLLS).with_time(limit)
+    }
+
+    /// Adds a time limit, measured on the runtime's timer clock.
+    ///
+    /// The limit is checked between polls, so one slow poll can overrun it.
+    #[must_use]
+    pub const fn with_time(mut self, limit: Duration) -> Self {
+        self.max_time = Some(limit);
+        self
+    }
+
+    /// Maximum task polls per turn.
+    #[must_use]
+    pub const fn max_polls(&self) -> usize {
+        self.max_polls
+    }
+
+    /// Maximum time per turn, if limited.
+    #[must_use]
+    pub const fn max_time(&self) -> Option<Duration> {
+        self.max_time
+    }
+}
+
+impl Default for TurnBudget {
+    fn default() -> Self {
+        Self::polls(DEFAULT_TURN_POLLS)
+    }
+}
+
+/// What one turn did and when the host should turn again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnResult {
+    /// Task polls performed during the turn.
+    pub polled: usize,
+    /// The turn stopped on its budget rather than running out of work.
+    pub budget_exhausted: bool,
+    /// Work is runnable right now; the host should turn again promptly.
+    pub more_work: bool,
+    /// Earliest timer or timed-lane deadline still pending.
+    pub next_deadline: Option<Time>,
+    /// Runtime time at the end of the turn.
+    pub now: Time,
+    /// No live tasks, regions, or obligations remain.
+    pub quiescent: bool,
+}
+
+impl TurnResult {
+    /// How long the host may wait before the next turn absent a wake.
+    ///
+    /// `Some(Duration::ZERO)` when work is pending, the time until
+    /// [`next_deadline`](Self::next_deadline) when a deadline is pending,
+    /// and `None` when only a wake can create new work.
+    #[must_use]
+    pub fn wait_hint(&self) -> Option<Duration> {
+        if self.more_work {
+            return Some(Duration::ZERO);
+        }
+        self.next_deadline
+            .map(|deadline| Duration::from_nanos(deadline.duration_since(self.now)))
+    }
+}
+
+/// Error returned by [`EmbeddedDriver::turn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnError {
+    /// An embedded turn is already running on this thread.
+    Reentrant,
+}
+
+impl fmt::Display for TurnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reentrant => write!(f, "embedded turn already running on this thread"),
+        }
+    }
+}
+
+impl std::error::Error for TurnError {}
+
+type WakeCallback = Arc<dyn Fn() + Send + Sync>;
+
+struct WakeInner {
+    pending: AtomicBool,
+    signals: AtomicU64,
+    callback: Mutex<Option<WakeCallback>>,
+    /// Reader and writer ends, created on the first `pollable()` call.
+    #[cfg(unix)]
+    pipe: Mutex<Option<(UnixStream, UnixStream)>>,
+}
+
+/// Tells the host that the embedded runtime has new work.
+///
+/// Raised whenever a task is woken or spawned from any thread while the
+/// driver is between turns, and cleared at the start of every turn. Signals
+/// coalesce: the callback runs and the pollable handle becomes readable only
+/// on the transition from clear to pending.
+#[derive(Clone)]
+pub struct WakeSignal {
+    inner: Arc<WakeInner>,
+}
+
+impl WakeSignal {
+    fn new() -> Self {
+        Self {
+            inner: Arc::new(WakeInner {
+                pending: AtomicBool::new(false),
+                signals: AtomicU64::new(0),
+                callback: Mutex::new(None),
+                #[cfg(unix)]
+                pipe: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Installs the callback run when the signal becomes pending,
+    /// replacing any previous one.
+    ///
+    /// The callback runs on the waking thread, possibly while runtime locks
+    /// are held: it must only notify the host loop (post a message, write an
+    /// eventfd) and never call back into the runtime.
+    pub fn on_wake<F>(&self, callback: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self.inner.callback.lock() = Some(Arc::new(callback));
+    }
+
+    /// Returns a socket that is readable while the signal is pending.
+    ///
+    /// Register it with the host's poller; [`EmbeddedDriver::turn`] drains it.
+    #[cfg(unix)]
+    pub fn pollable(&self) -> io::Result<UnixStream> {
+        let mut pipe = self.inner.pipe.lock();
+        if let Some((reader, _)) = pipe.as_ref() {
+            return reader.try_clone();
+        }
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        if self.is_pending() {
+            let _ = (&writer).write(&[1]);
+        }
+        let handle = reader.try_clone()?;
+        *pipe = Some((reader, writer));
+        drop(pipe);
+        Ok(handle)
+    }
+
+    /// Returns true if the runtime has been woken since the last turn.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.inner.pending.load(Ordering::Acquire)
+    }
+
+    /// Total wakeups observed, including coalesced ones.
+    #[must_use]
+    pub fn signals(&self) -> u64 {
+        self.inner.signals.load(Ordering::Relaxed)
+    }
+
+    fn raise(&self) {
+        self.inner.signals.fetch_add(1, Ordering::Relaxed);
+        if self.inner.pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        #[cfg(unix)]
+        {
+            let pipe = self.inner.pipe.lock();
+            if let Some((_, writer)) = pipe.as_ref() {
+                let _ = (&*writer).write(&[1]);
+            }
+        }
+        let callback = self.inner.callback.lock().clone();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+
+    fn clear(&self) {
+        if !self.inner.pending.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        #[cfg(unix)]
+        {
+            let pipe = self.inner.pipe.lock();
+            if let Some((reader, _)) = pipe.as_ref() {
+                let mut drain = [0_u8; 16];
+                while matches!((&*reader).read(&mut drain), Ok(n) if n > 0) {}
+            }
+        }
+    }
+}
+
+impl fmt::Debug for WakeSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WakeSignal")
+            .field("pending", &self.is_pending())
+            .field("signals", &self.signals())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A runtime whose only worker is driven by the host's event loop.
+///
+/// Built by [`RuntimeBuilder::build_embedded`](crate::runtime::RuntimeBuilder::build_embedded).
+/// See the [module docs](self) for the turn contract.
+pub struct EmbeddedDriver {
+    runtime: Runtime,
+    worker: ThreeLaneWorker,
+    wake: WakeSignal,
+}
+
+impl EmbeddedDriver {
+    pub(crate) fn new(runtime: Runtime, worker: ThreeLaneWorker) -> Self {
+        let wake = WakeSignal::new();
+        let hook = wake.clone();
+        worker.set_wake_hook(Arc::new(move || hook.raise()));
+        Self {
+            runtime,
+            worker,
+            wake,
+        }
+    }
+
+    /// Runs one bounded, non-blocking turn on the calling thread.
+    ///
+    /// Returns [`TurnError::Reentrant`] without doing any work when another
+    /// embedded turn is already running on this thread.
+    pub fn turn(&mut self, budget: TurnBudget) -> Result<TurnResult, TurnError> {
+        let _turn = TurnGuard::enter()?;
+        let _handle = ScopedRuntimeHandle::new(self.runtime.handle());
+        self.wake.clear();
+
+        let deadline = budget
+            .max_time()
+            .map(|limit| self.worker.scheduler_now() + limit);
+        let turn = self.worker.run_turn(budget.max_polls(), deadline);
+        Ok(TurnResult {
+            polled: turn.polled,
+            budget_exhausted: turn.budget_exhausted,
+            more_work: self.worker.has_pending_work(),
+            next_deadline: self.worker.next_wake_deadline(),
+            now: self.worker.scheduler_now(),
+            quiescent: self.runtime.is_quiescent(),
+        })
+    }
+
+    /// Returns true while an embedded turn is running on the calling thread.
+    #[must_use]
+    pub fn in_turn() -> bool {
+        IN_TURN.with(Cell::get)
+    }
+
+    /// The signal raised when the runtime has new work between turns.
+    #[must_use]
+    pub fn wake_signal(&self) -> &WakeSignal {
+        &self.wake
+    }
+
+    /// Returns a handle for spawning tasks, usable from any thread.
+    #[must_use]
+    pub fn handle(&self) -> RuntimeHandle {
+        self.runtime.handle()
+    }
+
+    /// The underlying runtime, for configuration and observability queries.
+    #[must_use]
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+}
+
+impl fmt::Debug for EmbeddedDriver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddedDriver")
+            .field("wake", &self.wake)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::runtime::RuntimeBuilder;
+    use crate::runtime::yield_now::yield_now;
+    use crate::types::{Budget, RegionId};
+    use std::sync::atomic::AtomicUsize;
+
+    type FireLog = Arc<Mutex<Vec<(usize, Time)>>>;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn spawn_sleeper(
+        runtime: &mut LabRuntime,
+        root: RegionId,
+        id: usize,
+        delays_ms: &'static [u64],
+        log: &FireLog,
+    ) {
+        let log = Arc::clone(log);
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                for delay in delays_ms {
+                    let now = crate::cx::Cx::current().map_or(Time::ZERO, |cx| cx.now());
+                    crate::time::sleep(now, Duration::from_millis(*delay)).await;
+                }
+                let now = crate::cx::Cx::current().map_or(Time::ZERO, |cx| cx.now());
+                log.lock().push((id, now));
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+    }
+
+    fn spawn_yielder(runtime: &mut LabRuntime, root: RegionId, yields: usize) {
+        let (task_id, _handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                for _ in 0..yields {
+                    yield_now().await;
+                }
+            })
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+    }
+
+    fn sleeper_scenario(seed: u64) -> (LabRuntime, FireLog) {
+        let mut runtime = LabRuntime::new(LabConfig::new(seed));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let log = FireLog::default();
+        spawn_sleeper(&mut runtime, root, 0, &[30], &log);
+        spawn_sleeper(&mut runtime, root, 1, &[5, 20], &log);
+        spawn_sleeper(&mut runtime, root, 2, &[12], &log);
+        spawn_sleeper(&mut runtime, root, 3, &[7, 7, 7], &log);
+        (runtime, log)
+    }
+
+    fn yield_many(count: usize) -> impl std::future::Future<Output = ()> + Send {
+        async move {
+            for _ in 0..count {
+                yield_now().await;
+            }
+        }
+    }
+
+    #[test]
+    fn lab_fixed_tick_host_fires_timers_on_first_tick_past_deadline() {
+        init_test("lab_fixed_tick_host_fires_timers_on_first_tick_past_deadline");
+        let mut runtime = LabRuntime::new(LabConfig::new(11));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let log = FireLog::default();
+        spawn_sleeper(&mut runtime, root, 0, &[10], &log);
+        spawn_sleeper(&mut runtime, root, 1, &[16], &log);
+        spawn_sleeper(&mut runtime, root, 2, &[40], &log);
+
+        let tick = Duration::from_millis(16);
+        let mut turns = 0;
+        while !runtime.turn(TurnBudget::default()).quiescent {
+            turns += 1;
+            assert!(turns < 100, "fixed-tick host never reached quiescence");
+            runtime.advance_time(tick.as_nanos() as u64);
+        }
+
+        let mut fired = log.lock().clone();
+        fired.sort_unstable();
+        let expected = vec![
+            (0, Time::from_millis(16)),
+            (1, Time::from_millis(16)),
+            (2, Time::from_millis(48)),
+        ];
+        crate::assert_with_log!(fired == expected, "tick-aligned firing", expected, fired);
+        crate::assert_with_log!(turns == 3, "turns until quiescent", 3, turns);
+        crate::test_complete!("lab_fixed_tick_host_fires_timers_on_first_tick_past_deadline");
+    }
+
+    #[test]
+    fn lab_turn_reports_exact_deadline_hints() {
+        init_test("lab_turn_reports_exact_deadline_hints");
+        let (mut runtime, log) = sleeper_scenario(3);
+
+        let mut hints = Vec::new();
+        loop {
+            let result = runtime.turn(TurnBudget::default());
+            if result.quiescent {
+                crate::assert_with_log!(
+                    result.wait_hint().is_none(),
+                    "no hint once quiescent",
+                    None::<Duration>,
+                    result.wait_hint()
+                );
+                break;
+            }
+            let hint = result.wait_hint().expect("sleepers leave a deadline pending");
+            hints.push(hint.as_millis());
+            runtime.advance_time_to(result.next_deadline.expect("deadline"));
+        }
+
+        // Deadlines at 5, 7, 12, 14, 21, 25, 30 ms.
+        let expected: Vec<u128> = vec![5, 2, 5, 2, 7, 4, 5];
+        crate::assert_with_log!(hints == expected, "hint sequence", expected, hints);
+        let fired = log.lock().len();
+        crate::assert_with_log!(fired == 4, "all sleepers fired", 4, fired);
+        crate::test_complete!("lab_turn_reports_exact_deadline_hints");
+    }
+
+    #[test]
+    fn lab_turn_respects_poll_budget() {
+        init_test("lab_turn_respects_poll_budget");
+        let mut runtime = LabRuntime::new(LabConfig::new(5));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        for _ in 0..6 {
+            spawn_yielder(&mut runtime, root, 3);
+        }
+
+        let first = runtime.turn(TurnBudget::polls(4));
+        crate::assert_with_log!(first.polled == 4, "first turn polls", 4, first.polled);
+        assert!(first.budget_exhausted, "first turn should hit its budget");
+        assert!(first.more_work, "yielders remain runnable");
+        crate::assert_with_log!(
+            first.wait_hint() == Some(Duration::ZERO),
+            "pending work asks for an immediate turn",
+            Some(Duration::ZERO),
+            first.wait_hint()
+        );
+
+        let mut total = first.polled;
+        let mut turns = 1;
+        loop {
+            let result = runtime.turn(TurnBudget::polls(4));
+            assert!(result.polled <= 4, "turn exceeded its poll budget");
+            total += result.polled;
+            turns += 1;
+            if !result.more_work {
+                assert!(result.quiescent, "yielders finish without timers");
+                break;
+            }
+        }
+        // Six tasks, four polls each (three yields plus completion).
+        crate::assert_with_log!(total == 24, "total polls", 24, total);
+        crate::assert_with_log!(turns == 6, "turns of four polls", 6, turns);
+        crate::test_complete!("lab_turn_respects_poll_budget");
+    }
+
+    #[test]
+    fn lab_turn_loop_matches_auto_advance() {
+        init_test("lab_turn_loop_matches_auto_advance");
+        let (mut reference, reference_log) = sleeper_scenario(42);
+        let report = reference.run_with_auto_advance();
+
+        let (mut hosted, hosted_log) = sleeper_scenario(42);
+        loop {
+            let result = hosted.turn(TurnBudget::polls(2));
+            if result.quiescent {
+                break;
+            }
+            if !result.more_work {
+                hosted.advance_time_to(result.next_deadline.expect("deadline"));
+            }
+        }
+
+        let expected = reference_log.lock().clone();
+        let actual = hosted_log.lock().clone();
+        crate::assert_with_log!(expected == actual, "completion order", expected, actual);
+        crate::assert_with_log!(
+            hosted.now() == report.time_end,
+            "final virtual time",
+            report.time_end,
+            hosted.now()
+        );
+        crate::test_complete!("lab_turn_loop_matches_auto_advance");
+    }
+
+    #[test]
+    fn cross_thread_spawn_raises_wake_signal() {
+        init_test("cross_thread_spawn_raises_wake_signal");
+        let mut driver = RuntimeBuilder::new()
+            .build_embedded()
+            .expect("embedded runtime");
+        let callbacks = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&callbacks);
+        driver.wake_signal().on_wake(move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+        #[cfg(unix)]
+        let mut pollable = driver.wake_signal().pollable().expect("pollable");
+
+        let idle = driver.turn(TurnBudget::default()).expect("turn");
+        assert!(!idle.more_work, "fresh runtime has no work");
+
+        let handle = driver.handle();
+        let join = std::thread::spawn(move || handle.spawn(async { 7_u32 }))
+            .join()
+            .expect("spawner thread");
+
+        assert!(driver.wake_signal().is_pending(), "spawn should raise the signal");
+        let fired = callbacks.load(Ordering::SeqCst);
+        crate::assert_with_log!(fired == 1, "coalesced callback", 1, fired);
+        #[cfg(unix)]
+        {
+            let mut byte = [0_u8; 1];
+            let read = pollable.read(&mut byte).expect("pollable is readable");
+            crate::assert_with_log!(read == 1, "pollable byte", 1, read);
+        }
+
+        for _ in 0..16 {
+            if join.is_finished() {
+                break;
+            }
+            driver.turn(TurnBudget::default()).expect("turn");
+        }
+        assert!(join.is_finished(), "spawned task should run inside turns");
+        crate::test_complete!("cross_thread_spawn_raises_wake_signal");
+    }
+
+    #[test]
+    fn embedded_turn_is_bounded() {
+        init_test("embedded_turn_is_bounded");
+        let mut driver = RuntimeBuilder::new()
+            .build_embedded()
+            .expect("embedded runtime");
+        let handle = driver.handle();
+        let joins: Vec<_> = (0..8).map(|_| handle.spawn(yield_many(4))).collect();
+
+        let result = driver.turn(TurnBudget::polls(3)).expect("turn");
+        assert!(result.polled <= 3, "turn exceeded its poll budget");
+        assert!(result.budget_exhausted, "eight yielders outlast three polls");
+        assert!(result.more_work, "work remains after a bounded turn");
+
+        for _ in 0..64 {
+            if joins.iter().all(|join| join.is_finished()) {
+                break;
+            }
+            let result = driver.turn(TurnBudget::polls(3)).expect("turn");
+            assert!(result.polled <= 3, "turn exceeded its poll budget");
+        }
+        assert!(joins.iter().all(|join| join.is_finished()), "all yielders finish");
+        crate::test_complete!("embedded_turn_is_bounded");
+    }
+
+    #[test]
+    fn nested_turn_is_rejected() {
+        init_test("nested_turn_is_rejected");
+        let mut outer = RuntimeBuilder::new()
+            .build_embedded()
+            .expect("outer runtime");
+        let inner = RuntimeBuilder::new()
+            .build_embedded()
+            .expect("inner runtime");
+        let inner = Arc::new(Mutex::new(inner));
+        let observed = Arc::new(Mutex::new(None));
+
+        let task_inner = Arc::clone(&inner);
+        let task_observed = Arc::clone(&observed);
+        let join = outer.handle().spawn(async move {
+            let in_turn = EmbeddedDriver::in_turn();
+            let nested = task_inner.lock().turn(TurnBudget::default());
+            *task_observed.lock() = Some((in_turn, nested.err()));
+        });
+        for _ in 0..16 {
+            if join.is_finished() {
+                break;
+            }
+            outer.turn(TurnBudget::default()).expect("outer turn");
+        }
+
+        let observed = *observed.lock();
+        let expected = Some((true, Some(TurnError::Reentrant)));
+        crate::assert_with_log!(observed == expected, "nested turn", expected, observed);
+        assert!(!EmbeddedDriver::in_turn(), "guard released after the turn");
+        let result = inner.lock().turn(TurnBudget::default());
+        assert!(result.is_ok(), "inner driver still usable outside a turn");
+        crate::test_complete!("nested_turn_is_rejected");
+    }
+}
@@ -27,10 +27,32 @@
 //! | `ASUPERSYNC_GOVERNOR_INTERVAL` | `u32` | `governor_interval` |
 //! | `ASUPERSYNC_ENABLE_ADAPTIVE_CANCEL_STREAK` | `bool` | `enable_adaptive_cancel_streak` |
 //! | `ASUPERSYNC_ADAPTIVE_CANCEL_EPOCH_STEPS` | `u32` | `adaptive_cancel_streak_epoch_steps` |
-
+//! | `ASUPERSYNC_REACTOR_BACKEND` | `auto`/`poll` | `reactor_backend` |
+//!
+//! `ASUPERSYNC_REACTOR_BACKEND` is also honored by
+//! [`RuntimeBuilder::build`](super::builder::RuntimeBuilder::build) without
+//! [`with_env_overrides`](super::builder::RuntimeBuilder::with_env_overrides),
+//! so CI can run existing I/O suites against the `poll(2)` fallback.
+//!
+//! Native chaos mode (see [`chaos`](super::chaos)) is read separately by
+//! [`chaos_from_env`] and only takes effect with the `chaos` feature:
+//!
+//! | Variable | Type | Maps to |
+//! |----------|------|---------|
+//! | `ASUPERSYNC_CHAOS` | `bool` | enables the profile |
+//! | `ASUPERSYNC_CHAOS_SEED` | `u64` | `NativeChaosConfig::new` (default: OS entropy) |
+//! | `ASUPERSYNC_CHAOS_YIELD_PROBABILITY` | `f64` | `with_yield_probability` |
+//! | `ASUPERSYNC_CHAOS_WAKE_SHUFFLE_PROBABILITY` | `f64` | `with_wake_shuffle_probability` |
+//! | `ASUPERSYNC_CHAOS_DELAY_PROBABILITY` | `f64` | `with_delay_probability` |
+//! | `ASUPERSYNC_CHAOS_MAX_DELAY_US` | `u64` | `with_delay_range(0..=max)` |
+//! | `ASUPERSYNC_CHAOS_EXCLUDE` | comma list | `exclude` per label |
+
+use crate::runtime::chaos::NativeChaosConfig;
 use crate::runtime::config::RuntimeConfig;
+use crate::runtime::reactor::ReactorBackendPreference;
 use crate::types::builder::BuildError;
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Environment variable capability interface for controlled environment access.
 ///
@@ -203,6 +225,22 @@ pub const ENV_GOVERNOR_INTERVAL: &str = "ASUPERSYNC_GOVERNOR_INTERVAL";
 pub const ENV_ENABLE_ADAPTIVE_CANCEL_STREAK: &str = "ASUPERSYNC_ENABLE_ADAPTIVE_CANCEL_STREAK";
 /// Environment variable name for adaptive cancel-streak epoch length.
 pub const ENV_ADAPTIVE_CANCEL_EPOCH_STEPS: &str = "ASUPERSYNC_ADAPTIVE_CANCEL_EPOCH_STEPS";
+/// Environment variable name for the reactor backend preference (`auto` or `poll`).
+pub const ENV_REACTOR_BACKEND: &str = ReactorBackendPreference::ENV_VAR;
+/// Environment variable name for enabling native chaos mode.
+pub const ENV_CHAOS: &str = "ASUPERSYNC_CHAOS";
+/// Environment variable name for the native chaos seed.
+pub const ENV_CHAOS_SEED: &str = "ASUPERSYNC_CHAOS_SEED";
+/// Environment variable name for the chaos pre-poll yield probability.
+pub const ENV_CHAOS_YIELD_PROBABILITY: &str = "ASUPERSYNC_CHAOS_YIELD_PROBABILITY";
+/// Environment variable name for the chaos wake-shuffle probability.
+pub const ENV_CHAOS_WAKE_SHUFFLE_PROBABILITY: &str = "ASUPERSYNC_CHAOS_WAKE_SHUFFLE_PROBABILITY";
+/// Environment variable name for the chaos delay probability.
+pub const ENV_CHAOS_DELAY_PROBABILITY: &str = "ASUPERSYNC_CHAOS_DELAY_PROBABILITY";
+/// Environment variable name for the chaos delay upper bound, in microseconds.
+pub const ENV_CHAOS_MAX_DELAY_US: &str = "ASUPERSYNC_CHAOS_MAX_DELAY_US";
+/// Environment variable name for comma-separated labels chaos must not perturb.
+pub const ENV_CHAOS_EXCLUDE: &str = "ASUPERSYNC_CHAOS_EXCLUDE";
 
 /// Apply environment variable overrides to a [`RuntimeConfig`].
 ///
@@ -256,6 +294,9 @@ pub fn apply_env_overrides(
         config.adaptive_cancel_streak_epoch_steps =
             parse_u32(ENV_ADAPTIVE_CANCEL_EPOCH_STEPS, &val)?;
     }
+    if let Some(val) = env_reader.read_env(ENV_REACTOR_BACKEND) {
+        config.reactor_backend = parse_reactor_backend(ENV_REACTOR_BACKEND, &val)?;
+    }
 
     // Blocking pool min/max are applied independently above. Normalize after
     // overrides so setting either value alone cannot leave min_threads greater
@@ -265,6 +306,48 @@ pub fn apply_env_overrides(
     Ok(())
 }
 
+/// Read a native chaos profile from the `ASUPERSYNC_CHAOS*` variables.
+///
+/// Returns `None` unless [`ENV_CHAOS`] is set to a true value. Intensity
+/// variables that are unset keep the [`NativeChaosConfig`] defaults.
+pub fn chaos_from_env(
+    env_reader: &dyn EnvReader,
+) -> Result<Option<NativeChaosConfig>, BuildError> {
+    let enabled = match env_reader.read_env(ENV_CHAOS) {
+        Some(val) => parse_bool(ENV_CHAOS, &val)?,
+        None => false,
+    };
+    if !enabled {
+        return Ok(None);
+    }
+    let mut chaos = match env_reader.read_env(ENV_CHAOS_SEED) {
+        Some(val) => NativeChaosConfig::new(parse_u64(ENV_CHAOS_SEED, &val)?),
+        None => NativeChaosConfig::from_entropy(),
+    };
+    if let Some(val) = env_reader.read_env(ENV_CHAOS_YIELD_PROBABILITY) {
+        let probability = parse_probability(ENV_CHAOS_YIELD_PROBABILITY, &val)?;
+        chaos = chaos.with_yield_probability(probability);
+    }
+    if let Some(val) = env_reader.read_env(ENV_CHAOS_WAKE_SHUFFLE_PROBABILITY) {
+        let probability = parse_probability(ENV_CHAOS_WAKE_SHUFFLE_PROBABILITY, &val)?;
+        chaos = chaos.with_wake_shuffle_probability(probability);
+    }
+    if let Some(val) = env_reader.read_env(ENV_CHAOS_DELAY_PROBABILITY) {
+        let probability = parse_probability(ENV_CHAOS_DELAY_PROBABILITY, &val)?;
+        chaos = chaos.with_delay_probability(probability);
+    }
+    if let Some(val) = env_reader.read_env(ENV_CHAOS_MAX_DELAY_US) {
+        let max = Duration::from_micros(parse_u64(ENV_CHAOS_MAX_DELAY_US, &val)?);
+        chaos = chaos.with_delay_range(Duration::ZERO..=max);
+    }
+    if let Some(val) = env_reader.read_env(ENV_CHAOS_EXCLUDE) {
+        for label in val.split(',').map(str::trim).filter(|label| !label.is_empty()) {
+            chaos = chaos.exclude(label);
+        }
+    }
+    Ok(Some(chaos))
+}
+
 fn parse_usize(var_name: &str, val: &str) -> Result<usize, BuildError> {
     val.trim().parse::<usize>().map_err(|e| {
         BuildError::custom(format!(
@@ -281,6 +364,23 @@ fn parse_u32(var_name: &str, val: &str) -> Result<u32, BuildError> {
     })
 }
 
+fn parse_u64(var_name: &str, val: &str) -> Result<u64, BuildError> {
+    val.trim().parse::<u64>().map_err(|e| {
+        BuildError::custom(format!(
+            "invalid value for {var_name}: expected u64, got {val:?} ({e})"
+        ))
+    })
+}
+
+fn parse_probability(var_name: &str, val: &str) -> Result<f64, BuildError> {
+    match val.trim().parse::<f64>() {
+        Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(probability),
+        _ => Err(BuildError::custom(format!(
+            "invalid value for {var_name}: expected probability in [0, 1], got {val:?}"
+        ))),
+    }
+}
+
 fn parse_bool(var_name: &str, val: &str) -> Result<bool, BuildError> {
     match val.trim().to_lowercase().as_str() {
         "true" | "1" | "yes" | "on" => Ok(true),
@@ -291,6 +391,17 @@ fn parse_bool(var_name: &str, val: &str) -> Result<bool, BuildError> {
     }
 }
 
+fn parse_reactor_backend(
+    var_name: &str,
+    val: &str,
+) -> Result<ReactorBackendPreference, BuildError> {
+    ReactorBackendPreference::parse(val).ok_or_else(|| {
+        BuildError::custom(format!(
+            "invalid value for {var_name}: expected reactor backend (auto/poll), got {val:?}"
+        ))
+    })
+}
+
 fn validate_thread_name_prefix(field_name: &'static str, val: &str) -> Result<(), BuildError> {
     if val.contains('\0') {
         return Err(BuildError::invalid_value(
@@ -528,6 +639,7 @@ mod tests {
             ENV_GOVERNOR_INTERVAL,
             ENV_ENABLE_ADAPTIVE_CANCEL_STREAK,
             ENV_ADAPTIVE_CANCEL_EPOCH_STEPS,
+            ENV_REACTOR_BACKEND,
         ] {
             // SAFETY: test helpers guard environment mutation with env_lock.
             unsafe { std::env::remove_var(var) };
@@ -657,6 +769,44 @@ mod tests {
         assert!(err.to_string().contains("NUL"), "unexpected error: {err}");
     }
 
+    #[test]
+    fn env_chaos_profile_requires_opt_in() {
+        let vars = |pairs: &[(&str, &str)]| {
+            TestEnvReader::new(
+                pairs
+                    .iter()
+                    .map(|(name, val)| ((*name).to_string(), (*val).to_string()))
+                    .collect(),
+            )
+        };
+
+        let unset = chaos_from_env(&vars(&[(ENV_CHAOS_SEED, "7")])).unwrap();
+        assert_eq!(unset, None, "intensity variables alone do not enable chaos");
+
+        let chaos = chaos_from_env(&vars(&[
+            (ENV_CHAOS, "1"),
+            (ENV_CHAOS_SEED, "7"),
+            (ENV_CHAOS_YIELD_PROBABILITY, "0.25"),
+            (ENV_CHAOS_MAX_DELAY_US, "50"),
+            (ENV_CHAOS_EXCLUDE, "mutex.lock, app.flush,"),
+        ]))
+        .unwrap()
+        .expect("chaos enabled");
+        let expected = NativeChaosConfig::new(7)
+            .with_yield_probability(0.25)
+            .with_delay_range(Duration::ZERO..=Duration::from_micros(50))
+            .exclude("mutex.lock")
+            .exclude("app.flush");
+        assert_eq!(chaos, expected);
+
+        let invalid = vars(&[(ENV_CHAOS, "on"), (ENV_CHAOS_DELAY_PROBABILITY, "1.5")]);
+        let err = chaos_from_env(&invalid).expect_err("probability above one must be rejected");
+        assert!(
+            err.to_string().contains(ENV_CHAOS_DELAY_PROBABILITY),
+            "unexpected error: {err}"
+        );
+    }
+
     #[test]
     fn env_overrides_steal_batch_size() {
         with_env(ENV_STEAL_BATCH_SIZE, "32", || {
@@ -828,6 +978,21 @@ mod tests {
         assert!(std::env::var(ENV_ADAPTIVE_CANCEL_EPOCH_STEPS).is_err());
     }
 
+    #[test]
+    fn env_overrides_reactor_backend() {
+        with_env(ENV_REACTOR_BACKEND, "Poll", || {
+            let mut config = RuntimeConfig::default();
+            apply_env_overrides(&mut config).expect("should apply reactor_backend env override");
+            assert_eq!(config.reactor_backend, ReactorBackendPreference::Poll);
+        });
+        with_env(ENV_REACTOR_BACKEND, "select", || {
+            let mut config = RuntimeConfig::default();
+            let msg = apply_env_overrides(&mut config).unwrap_err().to_string();
+            assert!(msg.contains(ENV_REACTOR_BACKEND), "{msg}");
+            assert!(msg.contains("select"), "{msg}");
+        });
+    }
+
     #[test]
     fn env_overrides_invalid_bool_returns_error() {
         with_env(ENV_ENABLE_PARKING, "maybe", || {
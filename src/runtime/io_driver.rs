@@ -39,14 +39,15 @@
 //! ```
 
 use crate::runtime::reactor::{
-    Event, Events, Interest, Reactor, SlabToken, Source, Token, TokenSlab,
+    Event, Events, Interest, Reactor, ReactorBackend, SlabToken, Source, Token, TokenSlab,
 };
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::io;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::task::Waker;
+use std::thread::JoinHandle;
 use std::time::Duration;
 
 /// Default capacity for the events buffer.
@@ -82,6 +83,110 @@ pub struct IoStats {
     pub deregistrations: u64,
 }
 
+impl IoStats {
+    fn accumulate(&mut self, other: &Self) {
+        self.polls += other.polls;
+        self.events_received += other.events_received;
+        self.wakers_dispatched += other.wakers_dispatched;
+        self.unknown_tokens += other.unknown_tokens;
+        self.registrations += other.registrations;
+        self.deregistrations += other.deregistrations;
+    }
+}
+
+const DISPATCH_LATENCY_BUCKETS: usize = 8;
+
+/// Upper bound on a single blocking poll made by a secondary reactor's
+/// dedicated thread, so a missed shutdown wake still terminates the thread.
+const REACTOR_POLLER_SLICE: Duration = Duration::from_millis(100);
+
+/// Pause after a failed poll on a secondary reactor, so a persistently
+/// failing backend does not turn its thread into a busy loop.
+const REACTOR_POLLER_ERROR_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Distribution of the delay between a reactor poll returning and its
+/// wakers having been woken.
+///
+/// Bucket `i` counts dispatches that took at most
+/// [`DispatchLatencyHistogram::UPPER_BOUNDS`]`[i]`; the last bucket counts
+/// everything slower. Polls that dispatched no waker are not recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatchLatencyHistogram {
+    /// Per-bucket dispatch counts.
+    pub counts: [u64; DISPATCH_LATENCY_BUCKETS],
+}
+
+impl DispatchLatencyHistogram {
+    /// Inclusive upper bound of each bucket except the overflow bucket.
+    pub const UPPER_BOUNDS: [Duration; DISPATCH_LATENCY_BUCKETS - 1] = [
+        Duration::from_micros(1),
+        Duration::from_micros(5),
+        Duration::from_micros(20),
+        Duration::from_micros(100),
+        Duration::from_micros(500),
+        Duration::from_millis(2),
+        Duration::from_millis(10),
+    ];
+
+    fn bucket_for(latency: Duration) -> usize {
+        Self::UPPER_BOUNDS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(DISPATCH_LATENCY_BUCKETS - 1)
+    }
+
+    /// Total dispatches recorded.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Per-reactor counters kept outside the driver lock.
+#[derive(Debug, Default)]
+struct ReactorCounters {
+    wakeups: AtomicU64,
+    spurious_polls: AtomicU64,
+    dispatch_latency: [AtomicU64; DISPATCH_LATENCY_BUCKETS],
+}
+
+impl ReactorCounters {
+    fn dispatch_latency_histogram(&self) -> DispatchLatencyHistogram {
+        DispatchLatencyHistogram {
+            counts: std::array::from_fn(|bucket| {
+                self.dispatch_latency[bucket].load(Ordering::Relaxed)
+            }),
+        }
+    }
+}
+
+/// Point-in-time metrics for one reactor of an [`IoDriverHandle`].
+///
+/// Counters are cumulative since the reactor was created; sample twice and
+/// divide the `events_dispatched` delta by the interval for a per-second
+/// rate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReactorMetrics {
+    /// Position of the reactor in its group (0 is the primary reactor).
+    pub reactor: usize,
+    /// Backend implementing the reactor (epoll, kqueue, the `poll(2)` fallback, ...).
+    pub backend: ReactorBackend,
+    /// I/O sources currently registered with the reactor.
+    pub registered_handles: usize,
+    /// Completed polls.
+    pub polls: u64,
+    /// Readiness events returned by the backend.
+    pub events_received: u64,
+    /// Events whose task waker was dispatched.
+    pub events_dispatched: u64,
+    /// Wake requests issued to the reactor through the handle.
+    pub wakeups: u64,
+    /// Successful polls that dispatched no waker.
+    pub spurious_polls: u64,
+    /// Poll-return to waker-dispatch latency.
+    pub dispatch_latency: DispatchLatencyHistogram,
+}
+
 /// Driver for I/O event processing.
 ///
 /// `IoDriver` owns the reactor and a token→waker mapping. It processes I/O
@@ -462,11 +567,75 @@ impl IoDriver {
 ///
 /// This wrapper provides interior mutability for registering and updating
 /// wakers from async I/O types while keeping the driver single-threaded.
+///
+/// A handle built with [`with_reactors`](Self::with_reactors) fronts several
+/// reactors. The handle itself polls the primary reactor (index 0) through
+/// the worker leader/follower loop; every additional reactor is polled by a
+/// dedicated thread. New registrations are spread round-robin unless the
+/// caller pins them with [`register_with_affinity`](Self::register_with_affinity).
+/// A registration stays on the reactor it was placed on for its lifetime.
 #[derive(Clone)]
 pub struct IoDriverHandle {
     inner: Arc<Mutex<IoDriver>>,
     reactor: Arc<dyn Reactor>,
     is_polling: Arc<AtomicBool>,
+    counters: Arc<ReactorCounters>,
+    /// Position of this reactor in its group (0 for the primary).
+    index: usize,
+    /// All reactors of a multi-reactor handle. `None` for single-reactor
+    /// handles and for the member handles stored inside the group.
+    group: Option<Arc<ReactorGroup>>,
+}
+
+/// The reactors behind a multi-reactor [`IoDriverHandle`], together with the
+/// threads polling the secondary ones.
+struct ReactorGroup {
+    members: Vec<IoDriverHandle>,
+    next: AtomicUsize,
+    shutdown: Arc<AtomicBool>,
+    pollers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl ReactorGroup {
+    fn spawn_poller(&self, member: IoDriverHandle) -> io::Result<()> {
+        let shutdown = Arc::clone(&self.shutdown);
+        let poller = std::thread::Builder::new()
+            .name(format!("asupersync-reactor-{}", member.index))
+            .spawn(move || {
+                while !shutdown.load(Ordering::Acquire) {
+                    if member
+                        .try_turn_with(Some(REACTOR_POLLER_SLICE), |_, _| {})
+                        .is_err()
+                    {
+                        std::thread::sleep(REACTOR_POLLER_ERROR_BACKOFF);
+                    }
+                }
+            })?;
+        self.pollers.lock().push(poller);
+        Ok(())
+    }
+
+    fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        let pollers = std::mem::take(&mut *self.pollers.lock());
+        for member in &self.members[1..] {
+            let _ = member.reactor.wake();
+        }
+        let current = std::thread::current().id();
+        for poller in pollers {
+            // A waker dispatched by a poller can drop the last handle on that
+            // very thread; it exits on its own once it sees the flag.
+            if poller.thread().id() != current {
+                let _ = poller.join();
+            }
+        }
+    }
+}
+
+impl Drop for ReactorGroup {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 struct PollingGuard<'a> {
@@ -518,7 +687,9 @@ impl std::fmt::Debug for IoDriverHandle {
             .field("inner", &self.inner)
             .field("reactor", &"<dyn Reactor>")
             .field("is_polling", &self.is_polling.load(Ordering::Relaxed))
-            .finish()
+            .field("index", &self.index)
+            .field("reactor_count", &self.reactor_count())
+            .finish_non_exhaustive()
     }
 }
 
@@ -530,7 +701,56 @@ impl IoDriverHandle {
             inner: Arc::new(Mutex::new(IoDriver::new(reactor.clone()))),
             reactor,
             is_polling: Arc::new(AtomicBool::new(false)),
+            counters: Arc::new(ReactorCounters::default()),
+            index: 0,
+            group: None,
+        }
+    }
+
+    /// Creates a handle fronting `primary` plus `additional` reactors.
+    ///
+    /// The returned handle polls `primary`; each additional reactor gets a
+    /// dedicated poll thread named `asupersync-reactor-<index>` that runs
+    /// until [`shutdown_reactors`](Self::shutdown_reactors) is called or the
+    /// last handle is dropped. With no additional reactors this is
+    /// [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// Returns the spawn error if a poll thread cannot be started; threads
+    /// already started are joined first.
+    pub fn with_reactors(
+        primary: Arc<dyn Reactor>,
+        additional: impl IntoIterator<Item = Arc<dyn Reactor>>,
+    ) -> io::Result<Self> {
+        let additional: Vec<_> = additional.into_iter().collect();
+        if additional.is_empty() {
+            return Ok(Self::new(primary));
+        }
+        let members: Vec<Self> = std::iter::once(primary)
+            .chain(additional)
+            .enumerate()
+            .map(|(index, reactor)| {
+                let mut member = Self::new(reactor);
+                member.index = index;
+                member
+            })
+            .collect();
+        let group = Arc::new(ReactorGroup {
+            members,
+            next: AtomicUsize::new(0),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            pollers: Mutex::new(Vec::new()),
+        });
+        for member in &group.members[1..] {
+            if let Err(err) = group.spawn_poller(member.clone()) {
+                group.shutdown();
+                return Err(err);
+            }
         }
+        let mut handle = group.members[0].clone();
+        handle.group = Some(group);
+        Ok(handle)
     }
 
     /// Creates a new handle with a custom events buffer capacity.
@@ -543,64 +763,169 @@ impl IoDriverHandle {
             ))),
             reactor,
             is_polling: Arc::new(AtomicBool::new(false)),
+            counters: Arc::new(ReactorCounters::default()),
+            index: 0,
+            group: None,
+        }
+    }
+
+    fn members(&self) -> &[Self] {
+        self.group
+            .as_ref()
+            .map_or(std::slice::from_ref(self), |group| group.members.as_slice())
+    }
+
+    /// Picks the reactor a new registration lands on. Explicit affinities are
+    /// taken modulo the reactor count so pinned code also runs unchanged on a
+    /// single-reactor runtime.
+    fn select_reactor(&self, affinity: Option<usize>) -> &Self {
+        let Some(group) = self.group.as_ref() else {
+            return self;
+        };
+        if group.shutdown.load(Ordering::Acquire) {
+            return self;
+        }
+        let index = affinity.unwrap_or_else(|| group.next.fetch_add(1, Ordering::Relaxed));
+        &group.members[index % group.members.len()]
+    }
+
+    /// Returns the number of reactors behind this handle.
+    #[must_use]
+    pub fn reactor_count(&self) -> usize {
+        self.members().len()
+    }
+
+    /// Stops and joins the poll threads of the secondary reactors.
+    ///
+    /// Registrations made afterwards land on the primary reactor. Idempotent;
+    /// a no-op for single-reactor handles.
+    pub fn shutdown_reactors(&self) {
+        if let Some(group) = self.group.as_ref() {
+            group.shutdown();
         }
     }
 
     /// Registers a source with the reactor and associates the waker.
+    ///
+    /// On a multi-reactor handle the source is placed round-robin.
+    ///
+    /// File descriptor exhaustion is reported as a typed
+    /// [`ErrorKind::FdExhausted`](crate::error::ErrorKind::FdExhausted) error
+    /// (see [`map_fd_exhaustion`](crate::error::map_fd_exhaustion)).
     pub fn register(
         &self,
         source: &dyn Source,
         interest: Interest,
         waker: Waker,
     ) -> io::Result<IoRegistration> {
+        self.register_with_affinity(source, interest, waker, None)
+    }
+
+    /// Registers a source on the reactor at index `affinity`, or round-robin
+    /// when `affinity` is `None`.
+    ///
+    /// Indices wrap modulo [`reactor_count`](Self::reactor_count), so the
+    /// same affinity maps to the same reactor for every source — handles of
+    /// one connection group pinned to one index share a reactor thread.
+    pub fn register_with_affinity(
+        &self,
+        source: &dyn Source,
+        interest: Interest,
+        waker: Waker,
+        affinity: Option<usize>,
+    ) -> io::Result<IoRegistration> {
+        let target = self.select_reactor(affinity);
         // Always nudge the reactor before mutating registrations. Relying on a
         // sampled `is_polling` flag is racy: a poller can transition into the
         // blocking wait right after this load, which is especially harmful for
         // io_uring because register/modify also need access to the shared ring.
-        let _ = self.reactor.wake();
+        let _ = target.wake_reactor();
         let token = {
-            let mut driver = self.inner.lock();
-            driver.register(source, interest, waker)?
+            let mut driver = target.inner.lock();
+            driver
+                .register(source, interest, waker)
+                .map_err(crate::error::map_fd_exhaustion)?
         };
         Ok(IoRegistration::new(
             token,
-            Arc::downgrade(&self.inner),
+            Arc::downgrade(&target.inner),
             interest,
-            self.reactor.clone(),
+            target.reactor.clone(),
         ))
     }
 
-    /// Updates the waker for an existing registration.
+    /// Updates the waker for an existing registration on the primary
+    /// reactor.
+    ///
+    /// Tokens are per reactor; registrations placed on a secondary reactor
+    /// must be updated through [`IoRegistration::update_waker`].
     #[must_use]
     pub fn update_waker(&self, token: Token, waker: Waker) -> bool {
         let mut driver = self.inner.lock();
         driver.update_waker(token, waker)
     }
 
-    /// Returns true if the driver has no registered wakers.
+    /// Returns true if no reactor behind this handle has registered wakers.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        let driver = self.inner.lock();
-        driver.is_empty()
+        self.members()
+            .iter()
+            .all(|member| member.inner.lock().is_empty())
     }
 
-    /// Returns the number of registered wakers.
+    /// Returns the number of registered wakers across all reactors.
     #[must_use]
     pub fn waker_count(&self) -> usize {
-        let driver = self.inner.lock();
-        driver.waker_count()
+        self.members()
+            .iter()
+            .map(|member| member.inner.lock().waker_count())
+            .sum()
+    }
+
+    fn wake_reactor(&self) -> io::Result<()> {
+        self.counters.wakeups.fetch_add(1, Ordering::Relaxed);
+        crate::runtime::metrics::record_reactor_wakeup();
+        self.reactor.wake()
     }
 
     /// Wakes the underlying reactor from another thread.
     pub fn wake(&self) -> io::Result<()> {
-        self.reactor.wake()
+        self.wake_reactor()
     }
 
-    /// Returns a snapshot of the current I/O stats.
+    /// Returns a snapshot of the current I/O stats, summed across reactors.
     #[must_use]
     pub fn stats(&self) -> IoStats {
-        let driver = self.inner.lock();
-        driver.stats().clone()
+        let mut stats = IoStats::default();
+        for member in self.members() {
+            stats.accumulate(member.inner.lock().stats());
+        }
+        stats
+    }
+
+    /// Returns per-reactor metrics, indexed by reactor position.
+    #[must_use]
+    pub fn reactor_metrics(&self) -> Vec<ReactorMetrics> {
+        self.members()
+            .iter()
+            .map(|member| {
+                let (registered_handles, stats) = {
+                    let driver = member.inner.lock();
+                    (driver.waker_count(), driver.stats().clone())
+                };
+                ReactorMetrics {
+                    reactor: member.index,
+                    backend: member.reactor.backend(),
+                    registered_handles,
+                    polls: stats.polls,
+                    events_received: stats.events_received,
+                    events_dispatched: stats.wakers_dispatched,
+                    wakeups: member.counters.wakeups.load(Ordering::Relaxed),
+                    spurious_polls: member.counters.spurious_polls.load(Ordering::Relaxed),
+                    dispatch_latency: member.counters.dispatch_latency_histogram(),
+                }
+            })
+            .collect()
     }
 
     /// Processes pending I/O events with a per-event callback.
@@ -645,6 +970,10 @@ impl IoDriverHandle {
             let mut guard = PollingGuard::new(self, events, true);
 
             let poll_result = self.reactor.poll(guard.events_mut(), timeout);
+            // Dispatch latency uses the host monotonic clock, which the
+            // browser target does not provide.
+            #[cfg(not(target_arch = "wasm32"))]
+            let dispatch_started = std::time::Instant::now();
 
             let (wakers, event_data) = {
                 let mut driver = self.inner.lock();
@@ -668,10 +997,26 @@ impl IoDriverHandle {
                 on_event(&event, interest);
             }
 
+            let dispatched = wakers.len();
             for waker in wakers {
                 waker.wake();
             }
 
+            if poll_result.is_ok() {
+                if dispatched == 0 {
+                    self.counters.spurious_polls.fetch_add(1, Ordering::Relaxed);
+                    crate::runtime::metrics::record_reactor_spurious_poll();
+                } else {
+                    crate::runtime::metrics::record_reactor_events_dispatched(dispatched as u64);
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        let bucket =
+                            DispatchLatencyHistogram::bucket_for(dispatch_started.elapsed());
+                        self.counters.dispatch_latency[bucket].fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+
             poll_result.map(Some)
         } else {
             Ok(None)
@@ -679,6 +1024,8 @@ impl IoDriverHandle {
     }
 
     /// Returns a lock guard for direct access to the driver.
+    ///
+    /// On a multi-reactor handle this is the primary reactor's driver.
     pub fn lock(&self) -> parking_lot::MutexGuard<'_, IoDriver> {
         self.inner.lock()
     }
@@ -2839,6 +3186,195 @@ mod tests {
             driver.deregister(token).expect("deregister should succeed");
             crate::test_complete!("io_driver_with_epoll_reactor_writable");
         }
+
+        fn epoll_group(reactors: usize) -> IoDriverHandle {
+            let primary: Arc<dyn Reactor> = Arc::new(EpollReactor::new().expect("create reactor"));
+            let additional = (1..reactors).map(|_| {
+                Arc::new(EpollReactor::new().expect("create reactor")) as Arc<dyn Reactor>
+            });
+            IoDriverHandle::with_reactors(primary, additional).expect("spawn reactor threads")
+        }
+
+        fn registered_per_reactor(handle: &IoDriverHandle) -> Vec<usize> {
+            handle
+                .reactor_metrics()
+                .iter()
+                .map(|metrics| metrics.registered_handles)
+                .collect()
+        }
+
+        #[test]
+        fn reactor_group_round_robin_spreads_registrations() {
+            super::init_test("reactor_group_round_robin_spreads_registrations");
+            let handle = epoll_group(3);
+            crate::assert_with_log!(
+                handle.reactor_count() == 3,
+                "reactor count",
+                3usize,
+                handle.reactor_count()
+            );
+
+            let pairs: Vec<_> = (0..6)
+                .map(|_| UnixStream::pair().expect("create socket pair"))
+                .collect();
+            let registrations: Vec<_> = pairs
+                .iter()
+                .map(|(sock, _)| {
+                    let (waker, _) = create_test_waker();
+                    handle
+                        .register(sock, Interest::READABLE, waker)
+                        .expect("register should succeed")
+                })
+                .collect();
+
+            let per_reactor = registered_per_reactor(&handle);
+            crate::assert_with_log!(
+                per_reactor == vec![2, 2, 2],
+                "round-robin placement",
+                vec![2usize, 2, 2],
+                per_reactor
+            );
+            crate::assert_with_log!(
+                handle.waker_count() == 6,
+                "waker count spans reactors",
+                6usize,
+                handle.waker_count()
+            );
+
+            drop(registrations);
+            crate::assert_with_log!(handle.is_empty(), "empty", true, handle.is_empty());
+            handle.shutdown_reactors();
+            crate::test_complete!("reactor_group_round_robin_spreads_registrations");
+        }
+
+        #[test]
+        fn reactor_group_affinity_pins_registrations() {
+            super::init_test("reactor_group_affinity_pins_registrations");
+            let handle = epoll_group(3);
+            let pairs: Vec<_> = (0..4)
+                .map(|_| UnixStream::pair().expect("create socket pair"))
+                .collect();
+
+            // Index 5 wraps to reactor 2, alongside the explicit pins.
+            let registrations: Vec<_> = pairs
+                .iter()
+                .zip([2, 2, 5, 2])
+                .map(|((sock, _), affinity)| {
+                    let (waker, _) = create_test_waker();
+                    handle
+                        .register_with_affinity(sock, Interest::READABLE, waker, Some(affinity))
+                        .expect("register should succeed")
+                })
+                .collect();
+
+            let per_reactor = registered_per_reactor(&handle);
+            crate::assert_with_log!(
+                per_reactor == vec![0, 0, 4],
+                "affinity placement",
+                vec![0usize, 0, 4],
+                per_reactor
+            );
+
+            drop(registrations);
+            handle.shutdown_reactors();
+            crate::test_complete!("reactor_group_affinity_pins_registrations");
+        }
+
+        #[test]
+        fn reactor_group_secondary_thread_dispatches_wakers() {
+            super::init_test("reactor_group_secondary_thread_dispatches_wakers");
+            let handle = epoll_group(2);
+            let (sock_read, mut sock_write) = UnixStream::pair().expect("create socket pair");
+            let (waker, waker_state) = create_test_waker();
+            let registration = handle
+                .register_with_affinity(&sock_read, Interest::READABLE, waker, Some(1))
+                .expect("register should succeed");
+
+            sock_write.write_all(b"hello").expect("write failed");
+
+            // Nobody polls the primary here: only the reactor-1 thread can
+            // dispatch this waker.
+            let deadline = std::time::Instant::now() + Duration::from_secs(5);
+            while !waker_state.flag.load(Ordering::SeqCst) {
+                assert!(
+                    std::time::Instant::now() < deadline,
+                    "secondary reactor never dispatched the waker"
+                );
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            let metrics = handle.reactor_metrics();
+            crate::assert_with_log!(
+                metrics[1].events_dispatched >= 1,
+                "reactor 1 dispatched",
+                true,
+                metrics[1].events_dispatched
+            );
+            crate::assert_with_log!(
+                metrics[1].dispatch_latency.total() >= 1,
+                "dispatch latency recorded",
+                true,
+                metrics[1].dispatch_latency.total()
+            );
+            crate::assert_with_log!(
+                metrics[0].events_dispatched == 0,
+                "primary idle",
+                0u64,
+                metrics[0].events_dispatched
+            );
+
+            drop(registration);
+            handle.shutdown_reactors();
+            crate::test_complete!("reactor_group_secondary_thread_dispatches_wakers");
+        }
+
+        #[test]
+        fn reactor_group_shutdown_joins_pollers() {
+            super::init_test("reactor_group_shutdown_joins_pollers");
+            let handle = epoll_group(3);
+            handle.shutdown_reactors();
+            // Idempotent.
+            handle.shutdown_reactors();
+
+            let (sock, _peer) = UnixStream::pair().expect("create socket pair");
+            let (waker, _) = create_test_waker();
+            let registration = handle
+                .register_with_affinity(&sock, Interest::READABLE, waker, Some(2))
+                .expect("register should succeed");
+            let per_reactor = registered_per_reactor(&handle);
+            crate::assert_with_log!(
+                per_reactor == vec![1, 0, 0],
+                "post-shutdown placement",
+                vec![1usize, 0, 0],
+                per_reactor
+            );
+
+            drop(registration);
+            crate::test_complete!("reactor_group_shutdown_joins_pollers");
+        }
+
+        #[test]
+        fn single_reactor_handle_reports_one_reactor() {
+            super::init_test("single_reactor_handle_reports_one_reactor");
+            let handle = epoll_group(1);
+            let (sock, _peer) = UnixStream::pair().expect("create socket pair");
+            let (waker, _) = create_test_waker();
+            let registration = handle
+                .register_with_affinity(&sock, Interest::READABLE, waker, Some(7))
+                .expect("register should succeed");
+
+            let metrics = handle.reactor_metrics();
+            crate::assert_with_log!(metrics.len() == 1, "one reactor", 1usize, metrics.len());
+            crate::assert_with_log!(
+                metrics[0].registered_handles == 1,
+                "affinity wraps onto the only reactor",
+                1usize,
+                metrics[0].registered_handles
+            );
+
+            drop(registration);
+            crate::test_complete!("single_reactor_handle_reports_one_reactor");
+        }
     }
 
     #[test]
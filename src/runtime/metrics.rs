@@ -27,8 +27,8 @@
 //!
 //! # Reading the counters
 //!
-//! Counters are process-global and monotonic (except the derived
-//! [`Metrics::active_timers`] gauge). Tests and the bench should read a
+//! Counters are process-global and monotonic (except the
+//! [`Metrics::active_timers`] and [`Metrics::open_fds`] gauges). Tests and the bench should read a
 //! [`snapshot`] before driving work and another after, then assert on the
 //! *delta*. That pattern is robust to other tests incrementing the same global
 //! counters in parallel; relying on absolute values is not.
@@ -39,8 +39,9 @@ use core::sync::atomic::{AtomicU64, Ordering};
 /// A point-in-time copy of the runtime instrumentation counters.
 ///
 /// Obtain one with [`snapshot`]. All fields are cumulative since process start
-/// except [`active_timers`](Self::active_timers), which is a derived gauge of
-/// timers that have been registered but not yet fired or cancelled.
+/// except the gauges [`active_timers`](Self::active_timers), derived from
+/// timers that have been registered but not yet fired or cancelled, and
+/// [`open_fds`](Self::open_fds), sampled when the snapshot is taken.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Metrics {
     /// OS threads spawned solely to drive a `Sleep`/timer future to completion.
@@ -89,6 +90,42 @@ pub struct Metrics {
     /// saturating at `0`. Catches cancellation leaks: it must return to its
     /// pre-register value once every armed timer has fired or been cancelled.
     pub active_timers: u64,
+    /// Listener accepts that failed with `EMFILE`/`ENFILE`.
+    pub fd_exhaustion_events: u64,
+    /// Pending connections a listener accepted and closed with its reserved
+    /// emergency descriptor while the descriptor table was full.
+    pub emergency_fd_sheds: u64,
+    /// Gauge of descriptors open in this process when the snapshot was taken.
+    ///
+    /// Best effort (`/proc/self/fd` on Linux); `0` when the platform has no
+    /// descriptor count probe.
+    pub open_fds: u64,
+    /// Object-pool acquisitions satisfied from a free-list, across all pools.
+    pub object_pool_hits: u64,
+    /// Object-pool acquisitions that fell back to the allocator.
+    pub object_pool_misses: u64,
+    /// Objects reset and accepted back into a free-list.
+    pub object_pool_recycled: u64,
+    /// Pooled objects released to the allocator when a pool was drained.
+    pub object_pool_drained: u64,
+    /// Reactor events whose waker was dispatched, across all reactors.
+    pub reactor_events_dispatched: u64,
+    /// Wake requests issued to a reactor blocked in (or entering) poll.
+    pub reactor_wakeups: u64,
+    /// Successful reactor polls that dispatched no waker (timeouts, explicit
+    /// wakes, and events for already-deregistered tokens).
+    pub reactor_spurious_polls: u64,
+    /// Reactors created with the portable `poll(2)` backend because the native
+    /// backend failed to initialize.
+    pub reactor_backend_fallbacks: u64,
+    /// Plan arenas reset for a new plan, across all arenas.
+    pub plan_arena_resets: u64,
+    /// Bytes occupied by the plans discarded at those resets, summed.
+    pub plan_arena_bytes_used: u64,
+    /// Largest single plan seen by any plan arena, in bytes.
+    pub plan_arena_high_water: u64,
+    /// Plan arena capacity releases by the shrink policy.
+    pub plan_arena_shrinks: u64,
 }
 
 impl core::fmt::Display for Metrics {
@@ -97,7 +134,13 @@ impl core::fmt::Display for Metrics {
             f,
             "timer_threads_spawned={} sched_yield_calls={} worker_spins={} \
              worker_parks={} worker_unparks={} timers_registered={} \
-             timers_fired={} timers_cancelled={} active_timers={}",
+             timers_fired={} timers_cancelled={} active_timers={} \
+             fd_exhaustion_events={} emergency_fd_sheds={} open_fds={} \
+             object_pool_hits={} object_pool_misses={} object_pool_recycled={} \
+             object_pool_drained={} reactor_events_dispatched={} \
+             reactor_wakeups={} reactor_spurious_polls={} \
+             reactor_backend_fallbacks={} plan_arena_resets={} plan_arena_bytes_used={} \
+             plan_arena_high_water={} plan_arena_shrinks={}",
             self.timer_threads_spawned,
             self.sched_yield_calls,
             self.worker_spins,
@@ -107,6 +150,21 @@ impl core::fmt::Display for Metrics {
             self.timers_fired,
             self.timers_cancelled,
             self.active_timers,
+            self.fd_exhaustion_events,
+            self.emergency_fd_sheds,
+            self.open_fds,
+            self.object_pool_hits,
+            self.object_pool_misses,
+            self.object_pool_recycled,
+            self.object_pool_drained,
+            self.reactor_events_dispatched,
+            self.reactor_wakeups,
+            self.reactor_spurious_polls,
+            self.reactor_backend_fallbacks,
+            self.plan_arena_resets,
+            self.plan_arena_bytes_used,
+            self.plan_arena_high_water,
+            self.plan_arena_shrinks,
         )
     }
 }
@@ -121,6 +179,20 @@ struct Counters {
     timers_registered: AtomicU64,
     timers_fired: AtomicU64,
     timers_cancelled: AtomicU64,
+    fd_exhaustion_events: AtomicU64,
+    emergency_fd_sheds: AtomicU64,
+    object_pool_hits: AtomicU64,
+    object_pool_misses: AtomicU64,
+    object_pool_recycled: AtomicU64,
+    object_pool_drained: AtomicU64,
+    reactor_events_dispatched: AtomicU64,
+    reactor_wakeups: AtomicU64,
+    reactor_spurious_polls: AtomicU64,
+    reactor_backend_fallbacks: AtomicU64,
+    plan_arena_resets: AtomicU64,
+    plan_arena_bytes_used: AtomicU64,
+    plan_arena_high_water: AtomicU64,
+    plan_arena_shrinks: AtomicU64,
 }
 
 #[cfg(feature = "runtime-metrics")]
@@ -133,6 +205,20 @@ static COUNTERS: Counters = Counters {
     timers_registered: AtomicU64::new(0),
     timers_fired: AtomicU64::new(0),
     timers_cancelled: AtomicU64::new(0),
+    fd_exhaustion_events: AtomicU64::new(0),
+    emergency_fd_sheds: AtomicU64::new(0),
+    object_pool_hits: AtomicU64::new(0),
+    object_pool_misses: AtomicU64::new(0),
+    object_pool_recycled: AtomicU64::new(0),
+    object_pool_drained: AtomicU64::new(0),
+    reactor_events_dispatched: AtomicU64::new(0),
+    reactor_wakeups: AtomicU64::new(0),
+    reactor_spurious_polls: AtomicU64::new(0),
+    reactor_backend_fallbacks: AtomicU64::new(0),
+    plan_arena_resets: AtomicU64::new(0),
+    plan_arena_bytes_used: AtomicU64::new(0),
+    plan_arena_high_water: AtomicU64::new(0),
+    plan_arena_shrinks: AtomicU64::new(0),
 };
 
 /// Record that an OS thread was spawned to drive a timer/`Sleep` future.
@@ -213,6 +299,140 @@ pub fn record_timer_cancelled() {
     COUNTERS.timers_cancelled.fetch_add(1, Ordering::Relaxed);
 }
 
+/// Record that a listener accept failed with `EMFILE`/`ENFILE`.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_fd_exhaustion() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS
+        .fd_exhaustion_events
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record that a listener shed a pending connection with its emergency
+/// descriptor.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_emergency_fd_shed() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.emergency_fd_sheds.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record an object-pool acquisition served from a free-list.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_object_pool_hit() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.object_pool_hits.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record an object-pool acquisition that fell back to the allocator.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_object_pool_miss() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.object_pool_misses.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record an object reset and accepted back into a pool free-list.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_object_pool_recycled() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.object_pool_recycled.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record that `count` pooled objects were released by a pool drain.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_object_pool_drained(count: u64) {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS
+        .object_pool_drained
+        .fetch_add(count, Ordering::Relaxed);
+    #[cfg(not(feature = "runtime-metrics"))]
+    let _ = count;
+}
+
+/// Record that a reactor poll dispatched `count` wakers.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_reactor_events_dispatched(count: u64) {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS
+        .reactor_events_dispatched
+        .fetch_add(count, Ordering::Relaxed);
+    #[cfg(not(feature = "runtime-metrics"))]
+    let _ = count;
+}
+
+/// Record a wake request issued to a reactor.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_reactor_wakeup() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.reactor_wakeups.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record a successful reactor poll that dispatched no waker.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_reactor_spurious_poll() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS
+        .reactor_spurious_polls
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record a reactor that fell back to the portable `poll(2)` backend.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_reactor_backend_fallback() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS
+        .reactor_backend_fallbacks
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record that a plan arena was reset, discarding a plan of `bytes_used`
+/// bytes.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_plan_arena_reset(bytes_used: u64) {
+    #[cfg(feature = "runtime-metrics")]
+    {
+        COUNTERS.plan_arena_resets.fetch_add(1, Ordering::Relaxed);
+        COUNTERS
+            .plan_arena_bytes_used
+            .fetch_add(bytes_used, Ordering::Relaxed);
+        COUNTERS
+            .plan_arena_high_water
+            .fetch_max(bytes_used, Ordering::Relaxed);
+    }
+    #[cfg(not(feature = "runtime-metrics"))]
+    let _ = bytes_used;
+}
+
+/// Record that a plan arena's shrink policy released capacity.
+///
+/// No-op unless the `runtime-metrics` feature is enabled.
+#[inline]
+pub fn record_plan_arena_shrink() {
+    #[cfg(feature = "runtime-metrics")]
+    COUNTERS.plan_arena_shrinks.fetch_add(1, Ordering::Relaxed);
+}
+
 /// Read the current runtime instrumentation counters.
 ///
 /// Returns an all-zero [`Metrics`] when the `runtime-metrics` feature is
@@ -235,6 +455,21 @@ pub fn snapshot() -> Metrics {
             timers_fired: fired,
             timers_cancelled: cancelled,
             active_timers: registered.saturating_sub(fired.saturating_add(cancelled)),
+            fd_exhaustion_events: COUNTERS.fd_exhaustion_events.load(Ordering::Relaxed),
+            emergency_fd_sheds: COUNTERS.emergency_fd_sheds.load(Ordering::Relaxed),
+            open_fds: crate::net::FdUsage::sample().map_or(0, |usage| usage.open),
+            object_pool_hits: COUNTERS.object_pool_hits.load(Ordering::Relaxed),
+            object_pool_misses: COUNTERS.object_pool_misses.load(Ordering::Relaxed),
+            object_pool_recycled: COUNTERS.object_pool_recycled.load(Ordering::Relaxed),
+            object_pool_drained: COUNTERS.object_pool_drained.load(Ordering::Relaxed),
+            reactor_events_dispatched: COUNTERS.reactor_events_dispatched.load(Ordering::Relaxed),
+            reactor_wakeups: COUNTERS.reactor_wakeups.load(Ordering::Relaxed),
+            reactor_spurious_polls: COUNTERS.reactor_spurious_polls.load(Ordering::Relaxed),
+            reactor_backend_fallbacks: COUNTERS.reactor_backend_fallbacks.load(Ordering::Relaxed),
+            plan_arena_resets: COUNTERS.plan_arena_resets.load(Ordering::Relaxed),
+            plan_arena_bytes_used: COUNTERS.plan_arena_bytes_used.load(Ordering::Relaxed),
+            plan_arena_high_water: COUNTERS.plan_arena_high_water.load(Ordering::Relaxed),
+            plan_arena_shrinks: COUNTERS.plan_arena_shrinks.load(Ordering::Relaxed),
         }
     }
     #[cfg(not(feature = "runtime-metrics"))]
@@ -259,6 +494,20 @@ pub fn reset() {
     COUNTERS.timers_registered.store(0, Ordering::Relaxed);
     COUNTERS.timers_fired.store(0, Ordering::Relaxed);
     COUNTERS.timers_cancelled.store(0, Ordering::Relaxed);
+    COUNTERS.fd_exhaustion_events.store(0, Ordering::Relaxed);
+    COUNTERS.emergency_fd_sheds.store(0, Ordering::Relaxed);
+    COUNTERS.object_pool_hits.store(0, Ordering::Relaxed);
+    COUNTERS.object_pool_misses.store(0, Ordering::Relaxed);
+    COUNTERS.object_pool_recycled.store(0, Ordering::Relaxed);
+    COUNTERS.object_pool_drained.store(0, Ordering::Relaxed);
+    COUNTERS.reactor_events_dispatched.store(0, Ordering::Relaxed);
+    COUNTERS.reactor_wakeups.store(0, Ordering::Relaxed);
+    COUNTERS.reactor_spurious_polls.store(0, Ordering::Relaxed);
+    COUNTERS.reactor_backend_fallbacks.store(0, Ordering::Relaxed);
+    COUNTERS.plan_arena_resets.store(0, Ordering::Relaxed);
+    COUNTERS.plan_arena_bytes_used.store(0, Ordering::Relaxed);
+    COUNTERS.plan_arena_high_water.store(0, Ordering::Relaxed);
+    COUNTERS.plan_arena_shrinks.store(0, Ordering::Relaxed);
 }
 
 #[cfg(test)]
@@ -277,6 +526,18 @@ mod tests {
         record_timer_registered();
         record_timers_fired(5);
         record_timer_cancelled();
+        record_fd_exhaustion();
+        record_emergency_fd_shed();
+        record_object_pool_hit();
+        record_object_pool_miss();
+        record_object_pool_recycled();
+        record_object_pool_drained(3);
+        record_reactor_events_dispatched(4);
+        record_reactor_wakeup();
+        record_reactor_spurious_poll();
+        record_reactor_backend_fallback();
+        record_plan_arena_reset(64);
+        record_plan_arena_shrink();
         assert_eq!(snapshot(), Metrics::default());
     }
 
@@ -304,6 +565,19 @@ mod tests {
         record_timer_registered();
         record_timers_fired(2);
         record_timer_cancelled();
+        record_fd_exhaustion();
+        record_emergency_fd_shed();
+        record_object_pool_hit();
+        record_object_pool_miss();
+        record_object_pool_recycled();
+        record_object_pool_drained(2);
+        record_reactor_events_dispatched(3);
+        record_reactor_wakeup();
+        record_reactor_spurious_poll();
+        record_reactor_backend_fallback();
+        record_plan_arena_reset(128);
+        record_plan_arena_reset(32);
+        record_plan_arena_shrink();
 
         let after = snapshot();
         assert!(after.timer_threads_spawned >= before.timer_threads_spawned + 1);
@@ -314,6 +588,20 @@ mod tests {
         assert!(after.timers_registered >= before.timers_registered + 3);
         assert!(after.timers_fired >= before.timers_fired + 2);
         assert!(after.timers_cancelled >= before.timers_cancelled + 1);
+        assert!(after.fd_exhaustion_events >= before.fd_exhaustion_events + 1);
+        assert!(after.emergency_fd_sheds >= before.emergency_fd_sheds + 1);
+        assert!(after.object_pool_hits >= before.object_pool_hits + 1);
+        assert!(after.object_pool_misses >= before.object_pool_misses + 1);
+        assert!(after.object_pool_recycled >= before.object_pool_recycled + 1);
+        assert!(after.object_pool_drained >= before.object_pool_drained + 2);
+        assert!(after.reactor_events_dispatched >= before.reactor_events_dispatched + 3);
+        assert!(after.reactor_wakeups >= before.reactor_wakeups + 1);
+        assert!(after.reactor_spurious_polls >= before.reactor_spurious_polls + 1);
+        assert!(after.reactor_backend_fallbacks >= before.reactor_backend_fallbacks + 1);
+        assert!(after.plan_arena_resets >= before.plan_arena_resets + 2);
+        assert!(after.plan_arena_bytes_used >= before.plan_arena_bytes_used + 160);
+        assert!(after.plan_arena_high_water >= 128);
+        assert!(after.plan_arena_shrinks >= before.plan_arena_shrinks + 1);
     }
 
     /// `active_timers` is always the saturating-consistent derivation of the
@@ -347,6 +635,21 @@ mod tests {
             "timers_fired",
             "timers_cancelled",
             "active_timers",
+            "fd_exhaustion_events",
+            "emergency_fd_sheds",
+            "open_fds",
+            "object_pool_hits",
+            "object_pool_misses",
+            "object_pool_recycled",
+            "object_pool_drained",
+            "reactor_events_dispatched",
+            "reactor_wakeups",
+            "reactor_spurious_polls",
+            "reactor_backend_fallbacks",
+            "plan_arena_resets",
+            "plan_arena_bytes_used",
+            "plan_arena_high_water",
+            "plan_arena_shrinks",
         ] {
             assert!(s.contains(key), "Display missing {key}: {s}");
         }
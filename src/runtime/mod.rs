@@ -4,19 +4,24 @@
 //!
 //! - [`config`]: Runtime configuration types
 //! - [`builder`]: Runtime builder and handles
+//! - [`embedded`]: Turn-based driver for hosts that own the main loop
 //! - [`state`]: Global runtime state (Σ = {regions, tasks, obligations, now})
 //! - [`scheduler`]: Three-lane priority scheduler
 //! - [`stored_task`]: Type-erased future storage
 //! - [`task_handle`]: TaskHandle for awaiting spawned task results
+//! - [`task_lifecycle`]: Start, state, and completion hooks for task handles
 //! - [`waker`]: Waker implementation with deduplication
 //! - [`timer`]: Timer heap for deadline management
 //! - [`deadline_monitor`]: Deadline monitoring for approaching timeouts
 //! - [`reactor`]: I/O reactor abstraction
 //! - [`io_driver`]: Reactor driver that dispatches readiness to wakers
 //! - [`region_heap`]: Region-owned heap allocator with quiescent reclamation
+//! - [`reclaim`]: Cooperative cache and buffer shedding under memory pressure
 //! - [`cache`]: Content-addressed artifact cache and zero-copy handoff policy
 //! - [`rch_health`]: Deterministic RCH worker health and cache-warm admission
 //! - [`pool_sizing`]: Pure queueing-theoretic pool sizing recommendations
+//! - [`shutdown_report`]: Structured shutdown reports with per-region timing
+//! - [`chaos`]: Feature-gated randomized perturbation of native scheduling
 //!
 //! # Runtime Builder
 //!
@@ -116,7 +121,9 @@
 //! - `blocking_threads(min, max)`: default = 0..0. Max is clamped to be >= min.
 //! - `enable_parking`: default = true. Disabling reduces wake latency at CPU cost.
 //! - `poll_budget`: default = 128. Lower for fairness, higher for throughput.
+//! - `object_pool_max_per_shard`: default = 256. Per-worker bound on recycled task records; 0 disables pooling.
 //! - `root_region_limits`: default = None. Admission limits applied to root region.
+//! - `max_live_tasks` / `spawn_soft_watermark`: default = unlimited. Spawns past the limit fail with `AtCapacity`.
 //! - `on_thread_start/stop`: lifecycle hooks; keep work minimal to avoid jitter.
 //! - `metrics(...)`: default = NoOp. Custom providers add instrumentation overhead.
 //! - `deadline_monitoring(...)`: disabled by default; enables warning callbacks.
@@ -125,11 +132,13 @@ pub mod blocking_pool;
 pub mod builder;
 pub mod cache;
 pub mod changepoint;
+pub mod chaos;
 pub mod config;
 pub mod deadline_monitor;
 #[cfg(test)]
 mod deadline_monitor_metamorphic_tests;
 pub mod effects;
+pub mod embedded;
 pub mod env_config;
 pub mod epoch_gc;
 pub mod epoch_gc_integration;
@@ -143,11 +152,14 @@ pub mod kernel;
 pub mod memory_residency;
 /// Feature-gated runtime instrumentation counters (timer/sched_yield/park).
 pub mod metrics;
+pub mod object_pool;
 pub mod obligation_table;
 pub mod panic_isolation;
+pub mod panic_strategy;
 pub mod pool_sizing;
 pub mod rch_health;
 pub mod reactor;
+pub mod reclaim;
 pub mod region_heap;
 #[cfg(test)]
 mod region_heap_metamorphic_tests;
@@ -162,15 +174,18 @@ pub mod scheduler;
 pub mod sharded_state;
 #[cfg(test)]
 pub mod sharded_state_conformance;
+pub mod shutdown_report;
 pub mod slo_policy;
 /// Async wrapper for blocking pool operations.
 pub mod spawn_blocking;
+pub mod spawn_capacity;
 /// Lock-free spawn-request intake decoupled from `RuntimeState`.
 pub mod spawn_mailbox;
 pub mod state;
 pub mod state_verifier;
 pub mod stored_task;
 pub mod task_handle;
+pub mod task_lifecycle;
 pub mod task_table;
 pub mod timer;
 pub mod waker;
@@ -186,7 +201,8 @@ pub mod local;
 pub use crate::record::RegionLimits;
 pub use crate::sync::{ContendedMutex, LockMetricsSnapshot};
 pub use blocking_pool::{
-    BlockingPool, BlockingPoolHandle, BlockingPoolOptions, BlockingTaskHandle,
+    BlockingKind, BlockingLaneMetrics, BlockingPool, BlockingPoolHandle, BlockingPoolOptions,
+    BlockingSpawnError, BlockingTaskHandle,
 };
 pub use builder::{
     BrowserRuntime, BrowserRuntimeBuildError, BrowserRuntimeBuilder, BrowserRuntimeSelectionResult,
@@ -205,14 +221,22 @@ pub use changepoint::{
     CusumDetector, MetricSample, PageHinkleyConfig, PageHinkleyDetector, RuntimeMetricSeries,
     SeriesDetector,
 };
-pub use config::{BlockingPoolConfig, RuntimeConfig, TraceStorageBudget, TraceStorageProfile};
+pub use config::{
+    BlockingLaneConfig, BlockingPoolConfig, BlockingQueueFullPolicy, RuntimeConfig,
+    TraceStorageBudget, TraceStorageProfile,
+};
 pub use deadline_monitor::{
     AdaptiveDeadlineConfig, DeadlineMonitor, DeadlineWarning, MonitorConfig, WarningReason,
 };
+pub use embedded::{
+    DEFAULT_TURN_POLLS, EmbeddedDriver, TurnBudget, TurnError, TurnResult, WakeSignal,
+};
 pub use epoch_tracker::{
     EpochConsistencyConfig, EpochConsistencyTracker, EpochConsistencyViolation, ModuleId,
 };
-pub use io_driver::{IoDriver, IoDriverHandle, IoRegistration};
+pub use io_driver::{
+    DispatchLatencyHistogram, IoDriver, IoDriverHandle, IoRegistration, ReactorMetrics,
+};
 pub use io_op::IoOp;
 pub use memory_residency::{
     MEMORY_RESIDENCY_ACCOUNTING_DEBUG_ENDPOINT,
@@ -225,6 +249,7 @@ pub use memory_residency::{
     MemoryResidencyReasonCode, MemoryResidencyRecordPoolCounters, MemoryResidencyTier,
     MemoryResidencyTierAccountingRow, ProofPackWarmthTelemetry,
 };
+pub use object_pool::{ObjectPoolConfig, ObjectPoolStats, Poolable, ShardedPool};
 pub use obligation_table::{
     ObligationAbortInfo, ObligationCommitInfo, ObligationLeakInfo, ObligationTable,
 };
@@ -232,6 +257,10 @@ pub use panic_isolation::{
     CleanupPhase, FinalizerType, MetricsProviderPanicExt, PanicContext, PanicIsolationConfig,
     PanicIsolationResult, PanicIsolator, PanicLocation,
 };
+pub use panic_strategy::{
+    CrashReportConfig, CrashReportInput, DEFAULT_CRASH_REPORT_EVENTS, PanicStrategy,
+    render_crash_report,
+};
 pub use pool_sizing::{
     POOL_SIZING_SCALE, PoolSizingAction, PoolSizingBounds, PoolSizingCandidateMetrics,
     PoolSizingControllerState, PoolSizingDecision, PoolSizingEstimator, PoolSizingMode,
@@ -243,6 +272,11 @@ pub use reactor::{
     BrowserReactor, BrowserReactorConfig, Event, Events, Interest, LabReactor, Reactor,
     Registration, Source, Token,
 };
+pub use reclaim::{
+    CgroupMemoryPressure, InjectedPressure, MemoryPressure, PressureSource, ReclaimConfig,
+    ReclaimCoordinator, ReclaimEvent, ReclaimFuture, ReclaimId, ReclaimPriority, ReclaimRound,
+    Reclaimable,
+};
 pub use region_heap::{HeapIndex, HeapRef, HeapStats, RegionHeap, global_alloc_count};
 pub use region_table::{RegionCreateError, RegionTable};
 pub use resource_cleanup_verifier::{
@@ -251,11 +285,20 @@ pub use resource_cleanup_verifier::{
 };
 pub use scheduler::Scheduler;
 pub use sharded_state::{ShardGuard, ShardedConfig, ShardedObservability, ShardedState};
+pub use shutdown_report::{
+    RegionShutdownSummary, SHUTDOWN_REPORT_SCHEMA_VERSION, SHUTDOWN_REPORT_SLOWEST_TASKS,
+    ShutdownBudgetUsage, ShutdownOutcomeCounts, ShutdownReport, ShutdownTaskOutcome,
+    TaskDrainSummary,
+};
 pub use slo_policy::{
     FourthWaveRuntimeBridgeDecision, SloRuntimePolicyBridge, SloRuntimePolicyBridgeDecision,
     SloRuntimePolicyBridgeRequest, SloRuntimeWorkKind,
 };
 pub use spawn_blocking::{spawn_blocking, spawn_blocking_io};
+pub use spawn_capacity::{
+    CapacityScope, CapacityWait, SpawnCapacityConfig, SpawnCapacityGate, SpawnCapacityMetrics,
+    SpawnPressureEvent, SpawnPressureKind,
+};
 pub use state::{
     ManualFinalizerReceipt, ManualFinalizerReceiptError, RuntimeSnapshot, RuntimeState, SpawnError,
 };
@@ -265,5 +308,8 @@ pub use state_verifier::{
 };
 pub use stored_task::StoredTask;
 pub use task_handle::{JoinError, TaskHandle};
+pub use task_lifecycle::{
+    CompletionHookError, MAX_COMPLETION_CALLBACKS, TaskLifecycleState, TaskStarted,
+};
 pub use task_table::TaskTable;
 pub use yield_now::yield_now;
@@ -0,0 +1,453 @@
+//! Sharded, bounded object pools for hot runtime structures.
+//!
+//! High-churn workloads (hundreds of thousands of short-lived tasks per
+//! second) spend a measurable share of their time in the allocator recycling
+//! the same few structure shapes. [`ShardedPool`] keeps a bounded free-list
+//! per worker shard so a record released on one worker is handed back to the
+//! next spawn on that same worker, falling back to the allocator when the
+//! shard is empty and dropping the object when the shard is full.
+//!
+//! # Reset discipline
+//!
+//! Every object is [`Recyclable::reset`] before it enters a free-list. A stale
+//! waker or region id surviving a reuse would silently attach a new task to
+//! an old task's wake path, so [`Poolable::is_pristine`] is checked with a
+//! `debug_assert!` both when an object is recycled and when it is handed out.
+//! Release builds pay nothing for the check.
+//!
+//! # Shard selection
+//!
+//! The shard is the current scheduler worker id modulo the shard count.
+//! Threads that are not runtime workers (the thread that builds the runtime,
+//! the lab runtime's single driver thread) share shard `0`, so the lab runtime
+//! exercises exactly the same pool as the native runtime.
+//!
+//! # Draining
+//!
+//! [`ShardedPool::drain`] empties every shard. The native runtime drains its
+//! pools once its workers have been joined so leak detectors that run at
+//! process exit see no pooled records.
+//!
+//! # Scope
+//!
+//! Only task records are pooled. Channels have no per-message node to
+//! recycle: `mpsc` and `broadcast` buffer messages in `VecDeque` rings and
+//! `mpsc` parks waiters in a token slab. The remaining per-wait allocation,
+//! the shared `Arc<RegisteredWaker>`, can outlive its slab entry, so it cannot
+//! be handed back to a free-list safely and is left to the allocator.
+
+use crate::runtime::metrics;
+use crate::runtime::reclaim::{ReclaimFuture, Reclaimable};
+use crate::util::Recyclable;
+use parking_lot::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default bound on pooled objects held by a single shard.
+pub const DEFAULT_MAX_POOLED_PER_SHARD: usize = 256;
+
+/// Object-pool sizing applied to the runtime's hot structures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectPoolConfig {
+    /// Maximum number of recycled objects a single shard may hold.
+    ///
+    /// `0` disables pooling: every acquisition falls back to the allocator
+    /// and every release drops the object.
+    pub max_pooled_per_shard: usize,
+    /// Number of shards. `0` means one shard per worker thread.
+    pub shards: usize,
+}
+
+impl ObjectPoolConfig {
+    /// Configuration that disables pooling entirely.
+    pub const DISABLED: Self = Self {
+        max_pooled_per_shard: 0,
+        shards: 1,
+    };
+
+    /// Returns the shard count to use for a runtime with `worker_threads`
+    /// workers.
+    #[must_use]
+    pub fn resolved_shards(&self, worker_threads: usize) -> usize {
+        if self.shards == 0 {
+            worker_threads.max(1)
+        } else {
+            self.shards
+        }
+    }
+}
+
+impl Default for ObjectPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_pooled_per_shard: DEFAULT_MAX_POOLED_PER_SHARD,
+            shards: 0,
+        }
+    }
+}
+
+/// An object that can verify it was fully reset before reuse.
+pub trait Poolable: Recyclable {
+    /// Returns `true` when no state from a previous use survives.
+    ///
+    /// Implementations should check every field that carries identity or
+    /// wake routing (ids, owners, wakers, queue links). The pool only calls
+    /// this from `debug_assert!`, so thoroughness costs nothing in release.
+    fn is_pristine(&self) -> bool;
+}
+
+/// Cumulative counters for one [`ShardedPool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectPoolStats {
+    /// Acquisitions satisfied from a free-list.
+    pub hits: u64,
+    /// Acquisitions that fell back to the allocator.
+    pub misses: u64,
+    /// Releases accepted into a free-list.
+    pub recycled: u64,
+    /// Releases dropped because pooling was disabled or the shard was full.
+    pub recycle_drops: u64,
+    /// Objects released back to the allocator by [`ShardedPool::drain`].
+    pub drained: u64,
+}
+
+impl ObjectPoolStats {
+    /// Fraction of acquisitions served from the pool, in `[0.0, 1.0]`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    recycled: AtomicU64,
+    recycle_drops: AtomicU64,
+    drained: AtomicU64,
+}
+
+/// A bounded free-list per worker shard with allocator fallback.
+#[derive(Debug)]
+pub struct ShardedPool<T> {
+    shards: Box<[Mutex<Vec<T>>]>,
+    max_per_shard: usize,
+    counters: PoolCounters,
+}
+
+impl<T: Poolable> ShardedPool<T> {
+    /// Creates a pool with `shards` free-lists of at most `max_per_shard`
+    /// objects each. A shard count of `0` is treated as `1`.
+    #[must_use]
+    pub fn new(shards: usize, max_per_shard: usize) -> Self {
+        let shards = (0..shards.max(1))
+            .map(|_| Mutex::new(Vec::new()))
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        Self {
+            shards,
+            max_per_shard,
+            counters: PoolCounters::default(),
+        }
+    }
+
+    /// Creates a pool sized by `config` for a runtime with `worker_threads`
+    /// workers.
+    #[must_use]
+    pub fn from_config(config: &ObjectPoolConfig, worker_threads: usize) -> Self {
+        Self::new(
+            config.resolved_shards(worker_threads),
+            config.max_pooled_per_shard,
+        )
+    }
+
+    fn lock_shard(&self, shard: usize) -> MutexGuard<'_, Vec<T>> {
+        self.shards[shard % self.shards.len()].lock()
+    }
+
+    fn current_shard(&self) -> usize {
+        crate::runtime::scheduler::three_lane::current_worker_id().unwrap_or(0)
+            % self.shards.len()
+    }
+
+    /// Takes a recycled object from the current worker's shard.
+    ///
+    /// Returns `None` (and counts a miss) when the shard is empty; the caller
+    /// then allocates a fresh object.
+    pub fn try_acquire(&self) -> Option<T> {
+        self.try_acquire_from(self.current_shard())
+    }
+
+    /// Takes a recycled object from an explicit shard.
+    pub fn try_acquire_from(&self, shard: usize) -> Option<T> {
+        let item = if self.max_per_shard == 0 {
+            None
+        } else {
+            self.lock_shard(shard).pop()
+        };
+        if let Some(item) = item {
+            debug_assert!(
+                item.is_pristine(),
+                "pooled {} handed out with stale state",
+                std::any::type_name::<T>()
+            );
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            metrics::record_object_pool_hit();
+            Some(item)
+        } else {
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            metrics::record_object_pool_miss();
+            None
+        }
+    }
+
+    /// Takes a recycled object or builds a fresh one with `factory`.
+    pub fn acquire_or_else<F>(&self, factory: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        self.try_acquire().unwrap_or_else(factory)
+    }
+
+    /// Resets `item` and returns it to the current worker's shard.
+    ///
+    /// Returns `false` when the object was dropped instead because pooling is
+    /// disabled or the shard is at its bound.
+    pub fn recycle(&self, item: T) -> bool {
+        self.recycle_into(self.current_shard(), item)
+    }
+
+    /// Resets `item` and returns it to an explicit shard.
+    pub fn recycle_into(&self, shard: usize, mut item: T) -> bool {
+        if self.max_per_shard == 0 {
+            self.counters.recycle_drops.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        item.reset();
+        debug_assert!(
+            item.is_pristine(),
+            "{}::reset left stale state behind",
+            std::any::type_name::<T>()
+        );
+        let rejected = {
+            let mut free = self.lock_shard(shard);
+            if free.len() < self.max_per_shard {
+                free.push(item);
+                None
+            } else {
+                Some(item)
+            }
+        };
+        if rejected.is_some() {
+            self.counters.recycle_drops.fetch_add(1, Ordering::Relaxed);
+            false
+        } else {
+            self.counters.recycled.fetch_add(1, Ordering::Relaxed);
+            metrics::record_object_pool_recycled();
+            true
+        }
+    }
+
+    /// Releases every pooled object back to the allocator.
+    ///
+    /// Objects are dropped after each shard lock is released, so a `Drop`
+    /// that re-enters the pool cannot deadlock. Returns the number drained.
+    pub fn drain(&self) -> usize {
+        let mut drained = 0;
+        for shard in 0..self.shards.len() {
+            let retired = std::mem::take(&mut *self.lock_shard(shard));
+            drained += retired.len();
+            drop(retired);
+        }
+        let count = drained as u64;
+        self.counters.drained.fetch_add(count, Ordering::Relaxed);
+        metrics::record_object_pool_drained(count);
+        drained
+    }
+
+    /// Releases up to `max_objects` pooled objects back to the allocator,
+    /// taking from the fullest shard first. Returns the number released.
+    pub fn shrink(&self, max_objects: usize) -> usize {
+        let mut released = 0;
+        while released < max_objects {
+            let fullest = (0..self.shards.len())
+                .max_by_key(|&shard| self.lock_shard(shard).len());
+            let retired = fullest.and_then(|shard| self.lock_shard(shard).pop());
+            let Some(retired) = retired else {
+                break;
+            };
+            drop(retired);
+            released += 1;
+        }
+        let count = released as u64;
+        self.counters.drained.fetch_add(count, Ordering::Relaxed);
+        metrics::record_object_pool_drained(count);
+        released
+    }
+}
+
+impl<T: Poolable + Send> Reclaimable for ShardedPool<T> {
+    /// Shrinks the free-lists, counting each object as `size_of::<T>()` bytes.
+    fn reclaim(&self, target_bytes: u64) -> ReclaimFuture<'_> {
+        let object_bytes = std::mem::size_of::<T>().max(1) as u64;
+        let wanted = usize::try_from(target_bytes.div_ceil(object_bytes)).unwrap_or(usize::MAX);
+        let released = self.shrink(wanted) as u64;
+        Box::pin(std::future::ready(released.saturating_mul(object_bytes)))
+    }
+}
+
+impl<T> ShardedPool<T> {
+    /// Returns the number of objects currently pooled across all shards.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    /// Returns `true` when no shard holds a pooled object.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of shards.
+    #[must_use]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the per-shard bound.
+    #[must_use]
+    pub const fn max_per_shard(&self) -> usize {
+        self.max_per_shard
+    }
+
+    /// Returns `true` when pooling is enabled.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.max_per_shard > 0
+    }
+
+    /// Returns a snapshot of this pool's counters.
+    #[must_use]
+    pub fn stats(&self) -> ObjectPoolStats {
+        ObjectPoolStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            recycled: self.counters.recycled.load(Ordering::Relaxed),
+            recycle_drops: self.counters.recycle_drops.load(Ordering::Relaxed),
+            drained: self.counters.drained.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Slot {
+        owner: Option<u64>,
+        payload: Vec<u8>,
+        reset_forgets_owner: bool,
+    }
+
+    impl Recyclable for Slot {
+        fn reset(&mut self) {
+            if !self.reset_forgets_owner {
+                self.owner = None;
+            }
+            self.payload.clear();
+        }
+    }
+
+    impl Poolable for Slot {
+        fn is_pristine(&self) -> bool {
+            self.owner.is_none() && self.payload.is_empty()
+        }
+    }
+
+    fn poisoned(owner: u64) -> Slot {
+        Slot {
+            owner: Some(owner),
+            payload: vec![0xA5; 32],
+            reset_forgets_owner: false,
+        }
+    }
+
+    #[test]
+    fn recycled_objects_come_back_pristine() {
+        let pool = ShardedPool::new(1, 4);
+        assert!(pool.recycle(poisoned(7)));
+        let slot = pool.try_acquire().expect("pool hit");
+        assert!(slot.is_pristine());
+        assert!(slot.payload.capacity() >= 32, "allocation is reused");
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "left stale state")]
+    fn incomplete_reset_is_caught_in_debug_builds() {
+        let pool = ShardedPool::new(1, 4);
+        let mut slot = poisoned(9);
+        slot.reset_forgets_owner = true;
+        let _ = pool.recycle(slot);
+    }
+
+    #[test]
+    fn shards_are_bounded_under_churn() {
+        let pool = ShardedPool::new(2, 3);
+        for round in 0..100 {
+            let _ = pool.recycle_into(round % 2, poisoned(round as u64));
+        }
+        assert_eq!(pool.len(), 6);
+        let stats = pool.stats();
+        assert_eq!(stats.recycled, 6);
+        assert_eq!(stats.recycle_drops, 94);
+    }
+
+    #[test]
+    fn counters_track_hits_misses_and_drains() {
+        let pool = ShardedPool::<Slot>::new(1, 8);
+        assert!(pool.try_acquire().is_none());
+        assert!(pool.recycle(poisoned(1)));
+        assert!(pool.recycle(poisoned(2)));
+        assert!(pool.try_acquire().is_some());
+        assert_eq!(pool.drain(), 1);
+        assert!(pool.is_empty());
+
+        let stats = pool.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.recycled, 2);
+        assert_eq!(stats.recycle_drops, 0);
+        assert_eq!(stats.drained, 1);
+        assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn disabled_pool_always_falls_back() {
+        let pool = ShardedPool::from_config(&ObjectPoolConfig::DISABLED, 4);
+        assert!(!pool.is_enabled());
+        assert!(!pool.recycle(poisoned(3)));
+        assert!(pool.try_acquire().is_none());
+        let stats = pool.stats();
+        assert_eq!(stats.recycle_drops, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn zero_shards_resolve_to_worker_count() {
+        let config = ObjectPoolConfig::default();
+        assert_eq!(config.resolved_shards(6), 6);
+        assert_eq!(config.resolved_shards(0), 1);
+        let pool = ShardedPool::<Slot>::from_config(&config, 3);
+        assert_eq!(pool.shard_count(), 3);
+        assert_eq!(pool.max_per_shard(), DEFAULT_MAX_POOLED_PER_SHARD);
+    }
+}
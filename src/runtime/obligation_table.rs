@@ -288,6 +288,13 @@ impl ObligationTable {
         self.obligations.get_mut(index)
     }
 
+    /// Returns the generation of the record occupying arena slot `slot`.
+    #[inline]
+    #[must_use]
+    pub(crate) fn occupant_generation(&self, slot: u32) -> Option<u32> {
+        self.obligations.occupant_generation(slot)
+    }
+
     /// Inserts a new obligation record into the arena.
     pub fn insert(&mut self, mut record: ObligationRecord) -> ArenaIndex {
         let is_pending = record.is_pending();
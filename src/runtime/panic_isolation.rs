@@ -9,7 +9,6 @@ use crate::types::{ObligationId, Outcome, RegionId, TaskId, outcome::PanicPayloa
 use std::backtrace::Backtrace;
 use std::collections::BTreeMap;
 use std::fmt;
-use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -317,7 +316,7 @@ impl PanicIsolator {
             return PanicIsolationResult::Skipped { reason, context };
         }
 
-        match std::panic::catch_unwind(AssertUnwindSafe(operation)) {
+        match crate::runtime::panic_strategy::catch_unwind(operation) {
             Ok(result) => PanicIsolationResult::Success(result),
             Err(panic_payload) => {
                 // br-asupersync-h0pfb4: Relaxed suffices for unique-counter
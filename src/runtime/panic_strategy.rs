@@ -0,0 +1,373 @@
+//! Panic strategy detection and `panic = "abort"` support.
+//!
+//! Under the default `panic = "unwind"` strategy the runtime contains task
+//! panics: the unwind stops at the poll boundary and the task completes with
+//! [`Outcome::Panicked`](crate::types::Outcome::Panicked), which supervision
+//! can observe and restart from.
+//!
+//! Under `panic = "abort"` there is nothing to contain. The first panic ends
+//! the process, so `Outcome::Panicked` never occurs and no supervisor ever
+//! restarts a panicking child. The runtime therefore skips its
+//! `catch_unwind` boundaries entirely in this mode instead of keeping code
+//! that only looks like isolation, and warns when a restart policy is
+//! configured for a supervised actor.
+//!
+//! What abort builds can still get is a record of the crash. A
+//! [`CrashReportConfig`] given to
+//! [`RuntimeBuilder::crash_report`](super::RuntimeBuilder::crash_report)
+//! installs a panic hook that runs synchronously before the abort: it flushes
+//! an optional [`RingBufferSink`] of evidence and writes a short report with
+//! the panic message, a region tree summary and the last structured trace
+//! events to the configured path.
+//!
+//! The strategy is detected with `cfg(panic = "...")`, i.e. the strategy this
+//! crate was compiled with, which is the one the final binary uses.
+
+use crate::evidence_sink::RingBufferSink;
+use crate::runtime::state::{RegionSnapshot, RuntimeSnapshot, RuntimeState};
+use crate::sync::ContendedMutex;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+/// Default number of trace events included in a crash report.
+pub const DEFAULT_CRASH_REPORT_EVENTS: usize = 32;
+
+/// How long the crash hook waits for the runtime state or evidence lock.
+const CRASH_LOCK_WAIT: Duration = Duration::from_millis(100);
+
+/// The panic strategy the crate was compiled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicStrategy {
+    /// Panics unwind; the runtime contains them at task boundaries.
+    Unwind,
+    /// Panics abort the process; nothing is contained.
+    Abort,
+}
+
+impl PanicStrategy {
+    /// Returns the strategy of the current build.
+    #[must_use]
+    pub const fn current() -> Self {
+        if cfg!(panic = "unwind") {
+            Self::Unwind
+        } else {
+            Self::Abort
+        }
+    }
+
+    /// Returns `true` if task panics become `Outcome::Panicked` rather than
+    /// ending the process.
+    #[must_use]
+    pub const fn contains_panics(self) -> bool {
+        matches!(self, Self::Unwind)
+    }
+
+    /// Returns the Cargo profile spelling, `"unwind"` or `"abort"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unwind => "unwind",
+            Self::Abort => "abort",
+        }
+    }
+}
+
+/// Runs `f` inside a panic containment boundary.
+///
+/// Under unwind this is `std::panic::catch_unwind`. Under abort it calls `f`
+/// directly: a panic never returns here, so there is no boundary to pretend.
+#[inline]
+pub(crate) fn catch_unwind<R>(f: impl FnOnce() -> R) -> std::thread::Result<R> {
+    #[cfg(panic = "unwind")]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+    }
+    #[cfg(not(panic = "unwind"))]
+    {
+        Ok(f())
+    }
+}
+
+/// Configuration of the pre-abort crash report.
+#[derive(Debug, Clone)]
+pub struct CrashReportConfig {
+    path: PathBuf,
+    max_events: usize,
+    evidence: Option<Arc<RingBufferSink>>,
+}
+
+impl CrashReportConfig {
+    /// Writes the report to `path`, replacing any previous report.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_events: DEFAULT_CRASH_REPORT_EVENTS,
+            evidence: None,
+        }
+    }
+
+    /// Sets how many of the most recent trace events the report includes.
+    #[must_use]
+    pub fn with_max_events(mut self, max_events: usize) -> Self {
+        self.max_events = max_events;
+        self
+    }
+
+    /// Flushes `sink` to its inner sink before the report is written.
+    #[must_use]
+    pub fn with_evidence_ring(mut self, sink: Arc<RingBufferSink>) -> Self {
+        self.evidence = Some(sink);
+        self
+    }
+
+    /// Returns the report path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of trace events included.
+    #[must_use]
+    pub const fn max_events(&self) -> usize {
+        self.max_events
+    }
+}
+
+/// Installs the crash report hook for a runtime.
+///
+/// Returns `false` without installing anything under unwind, where task panics
+/// are contained and a process-level report would misreport them as crashes.
+pub(crate) fn install_crash_report_hook(
+    config: CrashReportConfig,
+    state: Weak<ContendedMutex<RuntimeState>>,
+) -> bool {
+    if PanicStrategy::current().contains_panics() {
+        crate::tracing_compat::debug!(
+            path = %config.path.display(),
+            "crash report hook not installed: panics unwind in this build"
+        );
+        return false;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        write_crash_report(&config, &state, info);
+        previous(info);
+    }));
+    true
+}
+
+fn write_crash_report(
+    config: &CrashReportConfig,
+    state: &Weak<ContendedMutex<RuntimeState>>,
+    info: &PanicHookInfo<'_>,
+) {
+    let flushed = config
+        .evidence
+        .as_ref()
+        .map(|sink| sink.try_flush_for(CRASH_LOCK_WAIT));
+    let snapshot = state.upgrade().and_then(|state| {
+        let deadline = Instant::now() + CRASH_LOCK_WAIT;
+        loop {
+            if let Ok(guard) = state.try_lock() {
+                return Some(guard.snapshot());
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::yield_now();
+        }
+    });
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    let location = info.location().map(ToString::to_string);
+    let report = render_crash_report(&CrashReportInput {
+        message,
+        location: location.as_deref(),
+        thread: std::thread::current().name(),
+        snapshot: snapshot.as_ref(),
+        max_events: config.max_events,
+        evidence_flushed: flushed,
+    });
+    // Nothing useful can be done about a failed write: the process is about
+    // to abort either way.
+    let _ = std::fs::write(&config.path, report);
+}
+
+/// Inputs of a crash report.
+#[derive(Debug, Clone, Copy)]
+pub struct CrashReportInput<'a> {
+    /// Panic message.
+    pub message: &'a str,
+    /// Source location of the panic, if known.
+    pub location: Option<&'a str>,
+    /// Name of the panicking thread, if named.
+    pub thread: Option<&'a str>,
+    /// Runtime snapshot; `None` if the state lock could not be taken.
+    pub snapshot: Option<&'a RuntimeSnapshot>,
+    /// Maximum number of trace events to include.
+    pub max_events: usize,
+    /// Evidence entries flushed: `None` without a ring, `Some(None)` if its
+    /// lock could not be taken.
+    pub evidence_flushed: Option<Option<usize>>,
+}
+
+/// Renders a crash report as line-oriented `key: value` text.
+#[must_use]
+pub fn render_crash_report(input: &CrashReportInput<'_>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "asupersync crash report");
+    let _ = writeln!(out, "panic_strategy: {}", PanicStrategy::current().as_str());
+    let _ = writeln!(out, "message: {}", input.message);
+    let _ = writeln!(out, "location: {}", input.location.unwrap_or("unknown"));
+    let _ = writeln!(out, "thread: {}", input.thread.unwrap_or("unnamed"));
+    match input.evidence_flushed {
+        None => {}
+        Some(Some(count)) => {
+            let _ = writeln!(out, "evidence_flushed: {count}");
+        }
+        Some(None) => {
+            let _ = writeln!(out, "evidence_flushed: unavailable");
+        }
+    }
+    let Some(snapshot) = input.snapshot else {
+        let _ = writeln!(out, "runtime_state: unavailable");
+        return out;
+    };
+    let _ = writeln!(out, "regions: {}", snapshot.regions.len());
+    let _ = writeln!(out, "tasks: {}", snapshot.tasks.len());
+    let roots = snapshot.regions.iter().filter(|region| {
+        region
+            .parent_id
+            .is_none_or(|parent| !snapshot.regions.iter().any(|other| other.id == parent))
+    });
+    for root in roots {
+        render_region(&mut out, snapshot, root, 0);
+    }
+    let skip = snapshot.recent_events.len().saturating_sub(input.max_events);
+    let _ = writeln!(
+        out,
+        "events: {} of {}",
+        snapshot.recent_events.len() - skip,
+        snapshot.recent_events.len()
+    );
+    for event in &snapshot.recent_events[skip..] {
+        let _ = writeln!(
+            out,
+            "event: seq={} time_ns={} kind={:?}",
+            event.seq, event.time, event.kind
+        );
+    }
+    out
+}
+
+fn render_region(
+    out: &mut String,
+    snapshot: &RuntimeSnapshot,
+    region: &RegionSnapshot,
+    depth: usize,
+) {
+    let _ = writeln!(
+        out,
+        "region: {:indent$}{}.{} state={:?} tasks={} children={}",
+        "",
+        region.id.index,
+        region.id.generation,
+        region.state,
+        region.task_count,
+        region.child_count,
+        indent = depth * 2
+    );
+    for child in snapshot
+        .regions
+        .iter()
+        .filter(|child| child.parent_id == Some(region.id))
+    {
+        render_region(out, snapshot, child, depth + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::types::Budget;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn unwind_build_contains_panics_and_skips_hook() {
+        init_test("unwind_build_contains_panics_and_skips_hook");
+        assert_eq!(PanicStrategy::current(), PanicStrategy::Unwind);
+        assert!(PanicStrategy::current().contains_panics());
+        let caught = catch_unwind(|| panic!("contained"));
+        assert!(caught.is_err());
+        assert_eq!(catch_unwind(|| 7).ok(), Some(7));
+
+        let state = Arc::new(ContendedMutex::new("runtime_state", RuntimeState::new()));
+        let installed = install_crash_report_hook(
+            CrashReportConfig::new("unused-crash-report.txt"),
+            Arc::downgrade(&state),
+        );
+        assert!(!installed, "unwind builds must not install the abort hook");
+        crate::test_complete!("unwind_build_contains_panics_and_skips_hook");
+    }
+
+    #[test]
+    fn report_lists_region_tree_and_recent_events() {
+        init_test("report_lists_region_tree_and_recent_events");
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("child region");
+        let snapshot = state.snapshot();
+
+        let report = render_crash_report(&CrashReportInput {
+            message: "boom",
+            location: Some("src/main.rs:3:5"),
+            thread: Some("asupersync-worker-0"),
+            snapshot: Some(&snapshot),
+            max_events: 2,
+            evidence_flushed: Some(Some(4)),
+        });
+        assert!(report.starts_with("asupersync crash report\n"));
+        assert!(report.contains("message: boom\n"));
+        assert!(report.contains("location: src/main.rs:3:5\n"));
+        assert!(report.contains("evidence_flushed: 4\n"));
+        assert!(report.contains("regions: 2\n"));
+        let regions: Vec<&str> = report.lines().filter(|l| l.starts_with("region: ")).collect();
+        assert_eq!(regions.len(), 2, "{report}");
+        assert!(regions[1].starts_with("region:   "), "child is indented: {report}");
+        let events = report.lines().filter(|l| l.starts_with("event: ")).count();
+        assert!(events <= 2, "{report}");
+
+        let unavailable = render_crash_report(&CrashReportInput {
+            message: "boom",
+            location: None,
+            thread: None,
+            snapshot: None,
+            max_events: 2,
+            evidence_flushed: None,
+        });
+        assert!(unavailable.ends_with("runtime_state: unavailable\n"));
+        crate::test_complete!("report_lists_region_tree_and_recent_events");
+    }
+}
@@ -38,7 +38,7 @@
 //! - Thread safety: wasm32 is single-threaded but `Send + Sync` bounds
 //!   satisfied for API compatibility
 
-use super::{Events, Interest, Reactor, Source, Token};
+use super::{Events, Interest, Reactor, ReactorBackend, Source, Token};
 use parking_lot::{Mutex, MutexGuard};
 #[cfg(target_arch = "wasm32")]
 use std::cell::RefCell;
@@ -757,6 +757,10 @@ impl Reactor for BrowserReactor {
     fn registration_count(&self) -> usize {
         self.inner.registration_count()
     }
+
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Browser
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -67,7 +67,7 @@
 // compiler cannot verify file descriptor validity at compile time.
 #![allow(unsafe_code)]
 
-use super::{Event, Events, Interest, Reactor, Source, Token};
+use super::{Event, Events, Interest, Reactor, ReactorBackend, Source, Token};
 use hashbrown::HashMap;
 use hashbrown::hash_map::Entry;
 use parking_lot::Mutex;
@@ -617,6 +617,10 @@ impl Reactor for EpollReactor {
     fn registration_count(&self) -> usize {
         self.registration_count.load(Ordering::Relaxed)
     }
+
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Epoll
+    }
 }
 
 impl std::fmt::Debug for EpollReactor {
@@ -22,7 +22,7 @@ mod imp {
     #![allow(clippy::significant_drop_in_scrutinee)]
     #![allow(clippy::cast_sign_loss)]
 
-    use super::super::{Event, Events, Interest, Reactor, Source, Token};
+    use super::super::{Event, Events, Interest, Reactor, ReactorBackend, Source, Token};
     use io_uring::{IoUring, opcode, types};
     use parking_lot::Mutex;
     use smallvec::SmallVec;
@@ -853,6 +853,10 @@ mod imp {
         fn registration_count(&self) -> usize {
             self.state.lock().registrations.len()
         }
+
+        fn backend(&self) -> ReactorBackend {
+            ReactorBackend::IoUring
+        }
     }
 
     #[inline]
@@ -70,7 +70,7 @@
 // compiler cannot verify file descriptor validity at compile time.
 #![allow(unsafe_code)]
 
-use super::{Event, Events, Interest, Reactor, Source, Token};
+use super::{Event, Events, Interest, Reactor, ReactorBackend, Source, Token};
 use parking_lot::Mutex;
 use polling::{Event as PollEvent, Events as PollingEvents, PollMode, Poller};
 use std::collections::HashMap;
@@ -433,6 +433,10 @@ impl Reactor for KqueueReactor {
     fn registration_count(&self) -> usize {
         self.registrations.lock().len()
     }
+
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Kqueue
+    }
 }
 
 impl std::fmt::Debug for KqueueReactor {
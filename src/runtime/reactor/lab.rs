@@ -31,7 +31,7 @@
 //! assert_eq!(events.len(), 1);
 //! ```
 
-use super::{Event, Interest, Reactor, Source, Token};
+use super::{Event, Interest, Reactor, ReactorBackend, Source, Token};
 use crate::lab::chaos::{ChaosConfig, ChaosRng, ChaosStats};
 use crate::tracing_compat::debug;
 use crate::types::Time;
@@ -998,6 +998,10 @@ impl Reactor for LabReactor {
     fn registration_count(&self) -> usize {
         self.inner.lock().sockets.len()
     }
+
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Lab
+    }
 }
 
 #[cfg(all(test, unix))]
@@ -43,8 +43,14 @@
 //! | macOS/BSD | kqueue | `kqueue.rs` |
 //! | Windows | IOCP | `windows.rs` |
 //! | Browser/wasm32 | BrowserReactor | `browser.rs` |
+//! | Any Unix (fallback) | poll(2) | `poll.rs` |
 //! | Testing | virtual | `lab.rs` |
 //!
+//! [`select_reactor`] picks the native backend and falls back to the portable
+//! `PollReactor` when the native backend fails to initialize;
+//! [`ReactorBackendPreference::Poll`] (or `ASUPERSYNC_REACTOR_BACKEND=poll`)
+//! forces the fallback. [`Reactor::backend`] reports which one is running.
+//!
 //! # Public Export Contract
 //!
 //! The live `runtime::reactor` export graph is intentionally cfg-gated:
@@ -55,6 +61,7 @@
 //! | macOS/BSD | `KqueueReactor` | `kqueue.rs` | Live BSD-family backend only. |
 //! | Windows | `IocpReactor` | `windows.rs` | Live Windows backend only. |
 //! | wasm32 | `BrowserReactor` | `browser.rs` | Browser event-loop reactor. |
+//! | Unix | `PollReactor` | `poll.rs` | Portable `poll(2)` fallback; the default on Unix targets without a native backend. |
 //! | Deterministic tests | `LabReactor` | `lab.rs` | Virtual reactor for replayable tests. |
 //!
 //! Historical source files such as `src/runtime/reactor/uring.rs` and
@@ -136,6 +143,9 @@ pub mod kqueue;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+#[cfg(unix)]
+pub mod poll;
+
 pub use browser::{BrowserReactor, BrowserReactorConfig};
 pub use interest::Interest;
 pub use lab::{FaultConfig, LabReactor};
@@ -151,6 +161,9 @@ pub use epoll::EpollReactor;
 #[cfg(target_os = "windows")]
 pub use windows::IocpReactor;
 
+#[cfg(unix)]
+pub use poll::PollReactor;
+
 #[cfg(any(
     target_os = "macos",
     target_os = "freebsd",
@@ -377,6 +390,7 @@ impl IntoIterator for Events {
 /// | Linux | epoll | `epoll.rs` |
 /// | macOS/BSD | kqueue | `kqueue.rs` |
 /// | Windows | IOCP | `windows.rs` |
+/// | Any Unix (fallback) | poll(2) | `poll.rs` |
 /// | Testing | virtual | `lab.rs` |
 ///
 /// # Thread Safety
@@ -571,6 +585,201 @@ pub trait Reactor: Send + Sync {
     fn is_empty(&self) -> bool {
         self.registration_count() == 0
     }
+
+    /// Returns the backend implementing this reactor.
+    ///
+    /// Reported in the runtime's reactor metrics and startup log. Reactors
+    /// that do not override this report [`ReactorBackend::Custom`].
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Custom
+    }
+}
+
+/// The I/O notification backend behind a [`Reactor`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReactorBackend {
+    /// Linux epoll (`EpollReactor`).
+    Epoll,
+    /// Linux io_uring (`IoUringReactor`).
+    IoUring,
+    /// BSD/macOS kqueue.
+    Kqueue,
+    /// Windows I/O completion ports.
+    Iocp,
+    /// Portable `poll(2)` fallback (`PollReactor`).
+    Poll,
+    /// Browser event loop ([`BrowserReactor`]).
+    Browser,
+    /// Deterministic virtual reactor ([`LabReactor`]).
+    Lab,
+    /// A reactor that does not report its backend.
+    #[default]
+    Custom,
+}
+
+impl ReactorBackend {
+    /// Returns the stable lowercase name used in logs and metrics.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Epoll => "epoll",
+            Self::IoUring => "io_uring",
+            Self::Kqueue => "kqueue",
+            Self::Iocp => "iocp",
+            Self::Poll => "poll",
+            Self::Browser => "browser",
+            Self::Lab => "lab",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+impl std::fmt::Display for ReactorBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which backend [`select_reactor`] should create.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReactorBackendPreference {
+    /// The platform's native backend, falling back to `poll(2)` when it fails
+    /// to initialize.
+    #[default]
+    Auto,
+    /// Always use the portable `poll(2)` backend.
+    Poll,
+}
+
+impl ReactorBackendPreference {
+    /// Environment variable that overrides the configured preference.
+    pub const ENV_VAR: &'static str = "ASUPERSYNC_REACTOR_BACKEND";
+
+    /// Parses a preference name: `auto` (or `native`) and `poll`,
+    /// case-insensitively.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" | "native" => Some(Self::Auto),
+            "poll" => Some(Self::Poll),
+            _ => None,
+        }
+    }
+
+    /// Reads [`ENV_VAR`](Self::ENV_VAR), returning `None` when it is unset.
+    ///
+    /// Unrecognized values are logged and ignored.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(Self::ENV_VAR).ok()?;
+        let preference = Self::parse(&value);
+        if preference.is_none() {
+            crate::tracing_compat::warn!(
+                event = "reactor_backend_env_invalid",
+                value = %value,
+                "ignoring unrecognized {}; expected `auto` or `poll`",
+                Self::ENV_VAR
+            );
+        }
+        preference
+    }
+
+    /// Returns the preference name accepted by [`parse`](Self::parse).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Poll => "poll",
+        }
+    }
+}
+
+/// Outcome of [`select_reactor`].
+pub struct ReactorSelection {
+    /// The created reactor.
+    pub reactor: Arc<dyn Reactor>,
+    /// Backend of [`reactor`](Self::reactor).
+    pub backend: ReactorBackend,
+    /// Why the native backend was not used, when the selection fell back to
+    /// `poll(2)`.
+    pub fallback_reason: Option<String>,
+}
+
+impl std::fmt::Debug for ReactorSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReactorSelection")
+            .field("backend", &self.backend)
+            .field("fallback_reason", &self.fallback_reason)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates a reactor according to `preference`.
+///
+/// With [`ReactorBackendPreference::Auto`] the native backend is tried first
+/// (see [`create_reactor`] for the order). If it fails to initialize — a
+/// seccomp profile denying `epoll_create1`, an emulation layer without
+/// kqueue — the portable `PollReactor` is used
+/// instead, a `reactor_backend_fallback` warning records the reason, and
+/// [`ReactorSelection::fallback_reason`] carries it to the caller.
+///
+/// # Errors
+/// Returns an error if neither the requested nor the fallback backend can be
+/// created. The `poll(2)` fallback is only available on Unix targets.
+pub fn select_reactor(preference: ReactorBackendPreference) -> io::Result<ReactorSelection> {
+    select_reactor_with(preference, create_native_reactor)
+}
+
+/// [`select_reactor`] with an injectable native constructor, so tests can
+/// exercise the fallback path on hosts whose native backend works.
+pub(crate) fn select_reactor_with(
+    preference: ReactorBackendPreference,
+    native: impl FnOnce() -> io::Result<Arc<dyn Reactor>>,
+) -> io::Result<ReactorSelection> {
+    let fallback_reason = match preference {
+        ReactorBackendPreference::Poll => None,
+        ReactorBackendPreference::Auto => match native() {
+            Ok(reactor) => {
+                return Ok(ReactorSelection {
+                    backend: reactor.backend(),
+                    reactor,
+                    fallback_reason: None,
+                });
+            }
+            // Without a fallback, surface the native error unchanged.
+            Err(err) if !cfg!(unix) => return Err(err),
+            Err(err) => Some(err.to_string()),
+        },
+    };
+
+    let reactor = create_poll_reactor()?;
+    if let Some(_reason) = &fallback_reason {
+        crate::runtime::metrics::record_reactor_backend_fallback();
+        crate::tracing_compat::warn!(
+            event = "reactor_backend_fallback",
+            backend = ReactorBackend::Poll.as_str(),
+            reason = %_reason,
+            "native reactor backend failed to initialize; using the poll(2) fallback"
+        );
+    }
+    Ok(ReactorSelection {
+        reactor,
+        backend: ReactorBackend::Poll,
+        fallback_reason,
+    })
+}
+
+#[cfg(unix)]
+fn create_poll_reactor() -> io::Result<Arc<dyn Reactor>> {
+    Ok(Arc::new(PollReactor::new()?))
+}
+
+#[cfg(not(unix))]
+fn create_poll_reactor() -> io::Result<Arc<dyn Reactor>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the poll(2) reactor backend is only available on Unix targets",
+    ))
 }
 
 /// Create the best available reactor for the current platform.
@@ -582,11 +791,22 @@ pub trait Reactor: Send + Sync {
 /// - **Linux/Android**: io_uring (if enabled and available), otherwise epoll
 /// - **macOS/BSD**: kqueue
 /// - **Windows**: IOCP
+/// - **Other Unix**: poll(2)
+///
+/// If the native backend fails to initialize on a Unix target, the portable
+/// `PollReactor` is used instead (see [`select_reactor`]). Setting
+/// `ASUPERSYNC_REACTOR_BACKEND=poll` forces that fallback.
 ///
 /// # Errors
 /// Returns an error if no supported reactor backend can be created.
-#[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
+    let preference = ReactorBackendPreference::from_env().unwrap_or_default();
+    select_reactor(preference).map(|selection| selection.reactor)
+}
+
+/// Creates the native reactor for the current target, without fallback.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
     #[cfg(feature = "io-uring")]
     {
         if let Ok(reactor) = IoUringReactor::new() {
@@ -604,36 +824,45 @@ pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
     target_os = "netbsd",
     target_os = "dragonfly"
 ))]
-/// Creates the default reactor implementation for the current target.
-pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
+/// Creates the native reactor for the current target, without fallback.
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
     Ok(Arc::new(KqueueReactor::new()?))
 }
 
 #[cfg(target_os = "windows")]
-/// Creates the default reactor implementation for the current target.
-pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
+/// Creates the native reactor for the current target, without fallback.
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
     Ok(Arc::new(IocpReactor::new()?))
 }
 
 #[cfg(target_arch = "wasm32")]
-/// Creates the default reactor implementation for the current target.
-pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
+/// Creates the native reactor for the current target, without fallback.
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
     Ok(Arc::new(BrowserReactor::default()))
 }
 
-#[cfg(not(any(
-    target_os = "linux",
-    target_os = "android",
-    target_os = "macos",
-    target_os = "freebsd",
-    target_os = "openbsd",
-    target_os = "netbsd",
-    target_os = "dragonfly",
-    target_os = "windows",
-    target_arch = "wasm32"
-)))]
-/// Creates the default reactor implementation for the current target.
-pub fn create_reactor() -> io::Result<Arc<dyn Reactor>> {
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))
+))]
+/// Creates the native reactor for the current target, without fallback.
+///
+/// Unix targets without epoll or kqueue use `poll(2)` as their native backend.
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
+    Ok(Arc::new(PollReactor::new()?))
+}
+
+#[cfg(not(any(unix, target_os = "windows", target_arch = "wasm32")))]
+/// Creates the native reactor for the current target, without fallback.
+pub(crate) fn create_native_reactor() -> io::Result<Arc<dyn Reactor>> {
     Err(io::Error::new(
         io::ErrorKind::Unsupported,
         "no supported reactor backend for this platform",
@@ -687,6 +916,95 @@ mod tests {
         crate::test_complete!("create_reactor_factory");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn native_init_failure_falls_back_to_poll() {
+        init_test("native_init_failure_falls_back_to_poll");
+        let selection = select_reactor_with(ReactorBackendPreference::Auto, || {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "epoll_create1 denied by seccomp",
+            ))
+        })
+        .expect("poll fallback should come up");
+        crate::assert_with_log!(
+            selection.backend == ReactorBackend::Poll,
+            "fallback backend",
+            ReactorBackend::Poll,
+            selection.backend
+        );
+        crate::assert_with_log!(
+            selection.reactor.backend() == ReactorBackend::Poll,
+            "reactor reports poll backend",
+            ReactorBackend::Poll,
+            selection.reactor.backend()
+        );
+        let reason = selection.fallback_reason.clone().unwrap_or_default();
+        crate::assert_with_log!(
+            reason.contains("seccomp"),
+            "fallback reason names the native failure",
+            "epoll_create1 denied by seccomp",
+            reason
+        );
+        crate::test_complete!("native_init_failure_falls_back_to_poll");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forced_poll_preference_skips_native() {
+        init_test("forced_poll_preference_skips_native");
+        let selection = select_reactor_with(ReactorBackendPreference::Poll, || {
+            unreachable!("forced poll must not construct the native backend")
+        })
+        .expect("poll reactor should come up");
+        crate::assert_with_log!(
+            selection.backend == ReactorBackend::Poll && selection.fallback_reason.is_none(),
+            "forced poll without fallback reason",
+            ReactorBackend::Poll,
+            selection
+        );
+        crate::test_complete!("forced_poll_preference_skips_native");
+    }
+
+    #[test]
+    fn native_success_reports_its_backend() {
+        init_test("native_success_reports_its_backend");
+        let selection = select_reactor_with(ReactorBackendPreference::Auto, || {
+            let reactor: Arc<dyn Reactor> = Arc::new(LabReactor::new());
+            Ok(reactor)
+        })
+        .expect("native reactor should be used");
+        crate::assert_with_log!(
+            selection.backend == ReactorBackend::Lab && selection.fallback_reason.is_none(),
+            "native backend kept",
+            ReactorBackend::Lab,
+            selection
+        );
+        crate::test_complete!("native_success_reports_its_backend");
+    }
+
+    #[test]
+    fn reactor_backend_preference_parse() {
+        init_test("reactor_backend_preference_parse");
+        for (input, expected) in [
+            ("auto", Some(ReactorBackendPreference::Auto)),
+            ("Native", Some(ReactorBackendPreference::Auto)),
+            (" POLL ", Some(ReactorBackendPreference::Poll)),
+            ("select", None),
+            ("", None),
+        ] {
+            let parsed = ReactorBackendPreference::parse(input);
+            crate::assert_with_log!(parsed == expected, input, expected, parsed);
+        }
+        crate::assert_with_log!(
+            ReactorBackend::IoUring.to_string() == "io_uring",
+            "display",
+            "io_uring",
+            ReactorBackend::IoUring
+        );
+        crate::test_complete!("reactor_backend_preference_parse");
+    }
+
     // Event tests
     #[test]
     fn event_new() {
@@ -0,0 +1,918 @@
+//! Portable `poll(2)` reactor implementation.
+//!
+//! This module provides [`PollReactor`], a reactor built on nothing but
+//! `poll(2)`, `pipe(2)` and `fcntl(2)`. It is the fallback backend for hosts
+//! where the native reactor (epoll, io_uring, kqueue) cannot be initialized —
+//! seccomp-restricted containers, exotic Unix kernels, emulation layers — and
+//! the default backend on Unix targets without a native one.
+//!
+//! [`select_reactor`](super::select_reactor) switches to it automatically when
+//! the native backend fails to come up, logging a `reactor_backend_fallback`
+//! warning with the reason. It can also be forced with
+//! [`ReactorBackendPreference::Poll`](super::ReactorBackendPreference::Poll) or
+//! `ASUPERSYNC_REACTOR_BACKEND=poll`.
+//!
+//! # Safety
+//!
+//! This module uses `unsafe` code for the raw `libc` calls: `poll`, `pipe`,
+//! `read`, `write` and `fcntl`. Every call passes either a descriptor owned by
+//! the reactor (the self-pipe) or a registered descriptor the caller keeps
+//! open until deregistration, and every buffer is a live local or owned
+//! allocation whose length is passed alongside it.
+//!
+//! # Scalability
+//!
+//! Each `poll()` rebuilds the `pollfd` array from the registration table and
+//! the kernel scans that array linearly, so a wait costs O(registrations)
+//! regardless of how many descriptors are ready. That is cheap for the few
+//! hundred descriptors a typical embedded or CI deployment holds; with
+//! thousands of mostly idle registrations epoll and kqueue are clearly
+//! faster. The `scalability_smoke` test prints the measured crossover on the
+//! host it runs on.
+//!
+//! `select(2)` is never used, so descriptors numbered above `FD_SETSIZE`
+//! (1024) are fully supported.
+//!
+//! # Trigger Modes
+//!
+//! Registrations default to oneshot delivery like the other Unix backends: a
+//! registration that reports readiness is left out of later polls until
+//! `modify()` re-arms it. `poll(2)` is level-triggered only, so
+//! [`Interest::EDGE_TRIGGERED`] registrations stay armed and are reported on
+//! every poll while ready. That is a superset of edge notifications, which
+//! callers that drain to `WouldBlock` already tolerate.
+//!
+//! # Wakeups
+//!
+//! `wake()` writes one byte to a non-blocking self-pipe whose read end is
+//! always the first `pollfd`. A pending flag coalesces wakes so only the first
+//! `wake()` after a poll touches the pipe. Registration changes made while a
+//! poll is blocked also wake it, so the next wait sees the new set.
+
+// Allow unsafe code for the poll(2)/pipe(2)/fcntl(2) FFI calls.
+#![allow(unsafe_code)]
+
+use super::{Event, Events, Interest, Reactor, ReactorBackend, Source, Token};
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Registration bookkeeping for one descriptor.
+#[derive(Debug, Clone, Copy)]
+struct PollRegistration {
+    fd: RawFd,
+    interest: Interest,
+    /// Cleared when a oneshot registration fires; set again by `modify()`.
+    armed: bool,
+}
+
+#[derive(Debug, Default)]
+struct PollState {
+    tokens: HashMap<Token, PollRegistration>,
+    fds: HashMap<RawFd, Token>,
+}
+
+/// `pollfd` array and parallel token list, reused across polls.
+#[derive(Default)]
+struct PollScratch {
+    fds: Vec<libc::pollfd>,
+    tokens: Vec<Token>,
+}
+
+/// Portable reactor backed by `poll(2)`.
+///
+/// See the [module documentation](self) for scalability limits and trigger
+/// semantics.
+pub struct PollReactor {
+    state: Mutex<PollState>,
+    scratch: Mutex<PollScratch>,
+    wake_read: OwnedFd,
+    wake_write: OwnedFd,
+    /// Set by the first `wake()` after a poll; cleared once the pipe has been
+    /// drained empty.
+    wake_pending: AtomicBool,
+    /// Set while a poll is building its set or blocked in the kernel.
+    polling: AtomicBool,
+    registration_count: AtomicUsize,
+}
+
+impl PollReactor {
+    /// Creates a new poll reactor with its self-pipe.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the self-pipe cannot be created or configured.
+    pub fn new() -> io::Result<Self> {
+        let (wake_read, wake_write) = self_pipe()?;
+        Ok(Self {
+            state: Mutex::new(PollState::default()),
+            scratch: Mutex::new(PollScratch::default()),
+            wake_read,
+            wake_write,
+            wake_pending: AtomicBool::new(false),
+            polling: AtomicBool::new(false),
+            registration_count: AtomicUsize::new(0),
+        })
+    }
+
+    fn validate_supported_interest(interest: Interest) -> io::Result<()> {
+        if interest.is_dispatch() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Interest::DISPATCH is not supported by the poll reactor",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Converts registration interest into `pollfd.events`.
+    fn interest_to_poll_events(interest: Interest) -> libc::c_short {
+        let mut events = 0;
+        if interest.is_readable() {
+            events |= libc::POLLIN;
+        }
+        if interest.is_writable() {
+            events |= libc::POLLOUT;
+        }
+        if interest.is_priority() {
+            events |= libc::POLLPRI;
+        }
+        events
+    }
+
+    /// Converts `pollfd.revents` into readiness for a registration.
+    ///
+    /// Errors and hangups are always reported and also surface as the
+    /// registered directions, so a task waiting on either side wakes and
+    /// observes the failure from its next I/O call. Readiness the
+    /// registration did not ask for is masked out.
+    fn poll_events_to_interest(revents: libc::c_short, registered: Interest) -> Interest {
+        let failed = revents & (libc::POLLERR | libc::POLLNVAL) != 0;
+        let hangup = revents & libc::POLLHUP != 0;
+
+        let mut ready = Interest::NONE;
+        if revents & libc::POLLIN != 0 || failed || hangup {
+            ready = ready.add(Interest::READABLE);
+        }
+        if revents & libc::POLLOUT != 0 || failed || hangup {
+            ready = ready.add(Interest::WRITABLE);
+        }
+        let mut interest = ready & registered & (Interest::READABLE | Interest::WRITABLE);
+        if revents & libc::POLLPRI != 0 && registered.is_priority() {
+            interest = interest.add(Interest::PRIORITY);
+        }
+        if failed {
+            interest = interest.add(Interest::ERROR);
+        }
+        if hangup {
+            interest = interest.add(Interest::HUP);
+        }
+        interest
+    }
+
+    /// Wakes a blocked poll so it rebuilds its `pollfd` set.
+    fn refresh_in_flight_poll(&self) -> io::Result<()> {
+        if self.polling.load(Ordering::SeqCst) {
+            self.wake()?;
+        }
+        Ok(())
+    }
+
+    /// Drains the self-pipe after a wake was observed.
+    ///
+    /// The flag is cleared only after a read reports the pipe empty. While it
+    /// is still set, a racing `wake()` returns early and is coalesced into the
+    /// poll that is draining, which has already returned from the kernel.
+    /// Once it is clear, the next `wake()` writes a fresh byte. Clearing the
+    /// flag before the read would let the drain swallow that byte and leave
+    /// the flag set over an empty pipe, silencing every later wake.
+    fn drain_wake(&self) {
+        let mut buf = [0u8; 64];
+        loop {
+            // SAFETY: `buf` is a live local buffer of `buf.len()` bytes and
+            // `wake_read` is a descriptor owned by this reactor.
+            let n = unsafe {
+                libc::read(
+                    self.wake_read.as_raw_fd(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len(),
+                )
+            };
+            if n > 0 {
+                continue;
+            }
+            if n < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            break;
+        }
+        self.wake_pending.store(false, Ordering::SeqCst);
+    }
+
+    /// Runs `poll(2)` over `fds`, treating `EINTR` as a timeout.
+    fn wait(fds: &mut [libc::pollfd], timeout: Option<Duration>) -> io::Result<usize> {
+        // SAFETY: `fds` is an exclusively borrowed array of `fds.len()`
+        // initialized `pollfd` structs that outlives the call.
+        let rc = unsafe {
+            libc::poll(
+                fds.as_mut_ptr(),
+                fds.len() as libc::nfds_t,
+                timeout_to_millis(timeout),
+            )
+        };
+        if let Ok(ready) = usize::try_from(rc) {
+            return Ok(ready);
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::Interrupted {
+            return Ok(0);
+        }
+        Err(err)
+    }
+}
+
+impl Reactor for PollReactor {
+    fn register(&self, source: &dyn Source, token: Token, interest: Interest) -> io::Result<()> {
+        Self::validate_supported_interest(interest)?;
+
+        let raw_fd = source.as_raw_fd();
+        if raw_fd < 0 {
+            return Err(io::Error::from_raw_os_error(libc::EBADF));
+        }
+        // SAFETY: `F_GETFD` only queries descriptor flags; it fails with
+        // `EBADF` for descriptors that are not open.
+        if unsafe { libc::fcntl(raw_fd, libc::F_GETFD) } < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid file descriptor",
+            ));
+        }
+
+        let mut state = self.state.lock();
+        if state.tokens.contains_key(&token) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "token already registered",
+            ));
+        }
+        if state.fds.contains_key(&raw_fd) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "fd already registered",
+            ));
+        }
+        state.tokens.insert(
+            token,
+            PollRegistration {
+                fd: raw_fd,
+                interest,
+                armed: true,
+            },
+        );
+        state.fds.insert(raw_fd, token);
+        drop(state);
+
+        self.registration_count.fetch_add(1, Ordering::Relaxed);
+        self.refresh_in_flight_poll()
+    }
+
+    fn modify(&self, token: Token, interest: Interest) -> io::Result<()> {
+        Self::validate_supported_interest(interest)?;
+
+        let mut state = self.state.lock();
+        let Some(registration) = state.tokens.get_mut(&token) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "token not registered",
+            ));
+        };
+        registration.interest = interest;
+        registration.armed = true;
+        drop(state);
+
+        self.refresh_in_flight_poll()
+    }
+
+    fn deregister(&self, token: Token) -> io::Result<()> {
+        let mut state = self.state.lock();
+        let Some(registration) = state.tokens.remove(&token) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "token not registered",
+            ));
+        };
+        state.fds.remove(&registration.fd);
+        drop(state);
+
+        self.registration_count.fetch_sub(1, Ordering::Relaxed);
+        self.refresh_in_flight_poll()
+    }
+
+    fn poll(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
+        events.clear();
+
+        let mut scratch = self.scratch.lock();
+        let PollScratch { fds, tokens } = &mut *scratch;
+        fds.clear();
+        tokens.clear();
+        fds.push(libc::pollfd {
+            fd: self.wake_read.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        });
+
+        // Publish `polling` before snapshotting the table: a registration
+        // change that misses the snapshot is then guaranteed to see the flag
+        // and wake this poll.
+        self.polling.store(true, Ordering::SeqCst);
+        {
+            let state = self.state.lock();
+            for (token, registration) in &state.tokens {
+                if !registration.armed {
+                    continue;
+                }
+                fds.push(libc::pollfd {
+                    fd: registration.fd,
+                    events: Self::interest_to_poll_events(registration.interest),
+                    revents: 0,
+                });
+                tokens.push(*token);
+            }
+        }
+
+        let waited = Self::wait(fds, timeout);
+        self.polling.store(false, Ordering::SeqCst);
+        if waited? == 0 {
+            return Ok(0);
+        }
+
+        if fds[0].revents != 0 {
+            self.drain_wake();
+        }
+
+        // Ready registrations past capacity stay armed and are reported by
+        // the next poll.
+        let capacity = events.capacity().max(1);
+        let mut state = self.state.lock();
+        for (pollfd, token) in fds[1..].iter().zip(tokens.iter()) {
+            if pollfd.revents == 0 {
+                continue;
+            }
+            if events.len() >= capacity {
+                break;
+            }
+            // The table may have changed while the lock was released; only
+            // report registrations that still refer to the polled descriptor.
+            let Some(registration) = state.tokens.get_mut(token) else {
+                continue;
+            };
+            if registration.fd != pollfd.fd || !registration.armed {
+                continue;
+            }
+            let ready = Self::poll_events_to_interest(pollfd.revents, registration.interest);
+            if ready.is_empty() {
+                continue;
+            }
+            if !registration.interest.is_edge_triggered() {
+                registration.armed = false;
+            }
+            events.push(Event::new(*token, ready));
+        }
+
+        drop(state);
+        drop(scratch);
+        Ok(events.len())
+    }
+
+    fn wake(&self) -> io::Result<()> {
+        if self.wake_pending.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let byte = 1u8;
+        loop {
+            // SAFETY: writes one byte from a live local to the write end of
+            // the self-pipe, which this reactor owns.
+            let written = unsafe {
+                libc::write(
+                    self.wake_write.as_raw_fd(),
+                    (&raw const byte).cast(),
+                    1,
+                )
+            };
+            if written >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            match err.kind() {
+                io::ErrorKind::Interrupted => {}
+                // A full pipe already holds an unread wake.
+                io::ErrorKind::WouldBlock => return Ok(()),
+                _ => {
+                    self.wake_pending.store(false, Ordering::SeqCst);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn registration_count(&self) -> usize {
+        self.registration_count.load(Ordering::Relaxed)
+    }
+
+    fn backend(&self) -> ReactorBackend {
+        ReactorBackend::Poll
+    }
+}
+
+impl std::fmt::Debug for PollReactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reg_count = self.registration_count.load(Ordering::Relaxed);
+        f.debug_struct("PollReactor")
+            .field("registration_count", &reg_count)
+            .field("wake_pending", &self.wake_pending.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Converts a poll timeout to whole milliseconds, rounding up so a short
+/// non-zero timeout never degrades into a busy spin.
+fn timeout_to_millis(timeout: Option<Duration>) -> libc::c_int {
+    timeout.map_or(-1, |timeout| {
+        let millis = timeout.as_nanos().div_ceil(1_000_000);
+        libc::c_int::try_from(millis).unwrap_or(libc::c_int::MAX)
+    })
+}
+
+/// Creates the non-blocking, close-on-exec self-pipe used for wakeups.
+fn self_pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds: [libc::c_int; 2] = [0; 2];
+    // SAFETY: `fds` is a valid two-element buffer for pipe(2) to fill.
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: pipe(2) succeeded, so both descriptors are open and owned by
+    // nothing else.
+    let (read, write) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    set_nonblocking_cloexec(read.as_raw_fd())?;
+    set_nonblocking_cloexec(write.as_raw_fd())?;
+    Ok((read, write))
+}
+
+fn set_nonblocking_cloexec(fd: RawFd) -> io::Result<()> {
+    // SAFETY: fcntl on a descriptor owned by the caller, with integer
+    // arguments only.
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd_flags = libc::fcntl(fd, libc::F_GETFD);
+        if fd_flags < 0 || libc::fcntl(fd, libc::F_SETFD, fd_flags | libc::FD_CLOEXEC) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::test_utils::init_test_logging;
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+    use std::time::Instant;
+
+    fn init_test(name: &str) {
+        init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[derive(Debug)]
+    struct RawFdSource(RawFd);
+
+    impl AsRawFd for RawFdSource {
+        fn as_raw_fd(&self) -> RawFd {
+            self.0
+        }
+    }
+
+    /// Raises the soft descriptor limit toward `target` as far as the hard
+    /// limit allows and returns the resulting soft limit.
+    fn raise_nofile_limit(target: libc::rlim_t) -> libc::rlim_t {
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: `limit` is a valid out-pointer for getrlimit.
+        if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &raw mut limit) } != 0 {
+            return 0;
+        }
+        if limit.rlim_cur < target && limit.rlim_max > limit.rlim_cur {
+            let raised = libc::rlimit {
+                rlim_cur: limit.rlim_max.min(target),
+                rlim_max: limit.rlim_max,
+            };
+            // SAFETY: `raised` is a valid in-pointer for setrlimit.
+            if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raw const raised) } == 0 {
+                limit = raised;
+            }
+        }
+        limit.rlim_cur
+    }
+
+    fn has_readable(events: &Events, token: Token) -> bool {
+        events
+            .iter()
+            .any(|event| event.token == token && event.is_readable())
+    }
+
+    #[test]
+    fn register_modify_deregister() {
+        init_test("register_modify_deregister");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let (sock1, _sock2) = UnixStream::pair().expect("failed to create unix stream pair");
+        let token = Token::new(7);
+
+        reactor
+            .register(&sock1, token, Interest::READABLE)
+            .expect("register failed");
+        crate::assert_with_log!(
+            reactor.registration_count() == 1,
+            "registration count",
+            1usize,
+            reactor.registration_count()
+        );
+
+        let dup = reactor
+            .register(&sock1, Token::new(8), Interest::READABLE)
+            .expect_err("same fd under a new token should be rejected");
+        crate::assert_with_log!(
+            dup.kind() == io::ErrorKind::AlreadyExists,
+            "duplicate fd rejected",
+            io::ErrorKind::AlreadyExists,
+            dup.kind()
+        );
+
+        let dispatch = reactor
+            .modify(token, Interest::dispatch())
+            .expect_err("dispatch interest should be rejected");
+        crate::assert_with_log!(
+            dispatch.kind() == io::ErrorKind::InvalidInput,
+            "dispatch rejected",
+            io::ErrorKind::InvalidInput,
+            dispatch.kind()
+        );
+
+        reactor
+            .modify(token, Interest::WRITABLE)
+            .expect("modify failed");
+        reactor.deregister(token).expect("deregister failed");
+        let missing = reactor
+            .deregister(token)
+            .expect_err("second deregister should fail");
+        crate::assert_with_log!(
+            missing.kind() == io::ErrorKind::NotFound,
+            "unknown token",
+            io::ErrorKind::NotFound,
+            missing.kind()
+        );
+        crate::assert_with_log!(reactor.is_empty(), "empty", true, reactor.is_empty());
+
+        let bad = reactor
+            .register(&RawFdSource(-1), Token::new(9), Interest::READABLE)
+            .expect_err("negative fd should be rejected");
+        crate::assert_with_log!(
+            bad.raw_os_error() == Some(libc::EBADF),
+            "negative fd",
+            Some(libc::EBADF),
+            bad.raw_os_error()
+        );
+        crate::test_complete!("register_modify_deregister");
+    }
+
+    #[test]
+    fn oneshot_fire_then_silence_until_rearm() {
+        init_test("oneshot_fire_then_silence_until_rearm");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let (sock1, mut sock2) = UnixStream::pair().expect("failed to create unix stream pair");
+        let token = Token::new(1);
+        reactor
+            .register(&sock1, token, Interest::READABLE)
+            .expect("register failed");
+        sock2.write_all(b"ping").expect("write failed");
+
+        let mut events = Events::with_capacity(8);
+        reactor
+            .poll(&mut events, Some(Duration::from_millis(100)))
+            .expect("poll failed");
+        let fired = has_readable(&events, token);
+        crate::assert_with_log!(fired, "first poll reports readable", true, fired);
+
+        // Data is still unread, but the oneshot registration is disarmed.
+        let count = reactor
+            .poll(&mut events, Some(Duration::from_millis(20)))
+            .expect("poll failed");
+        crate::assert_with_log!(count == 0, "silent until re-armed", 0usize, count);
+
+        reactor
+            .modify(token, Interest::READABLE)
+            .expect("re-arm failed");
+        reactor
+            .poll(&mut events, Some(Duration::from_millis(100)))
+            .expect("poll failed");
+        let refired = has_readable(&events, token);
+        crate::assert_with_log!(refired, "re-armed poll reports readable", true, refired);
+        crate::test_complete!("oneshot_fire_then_silence_until_rearm");
+    }
+
+    #[test]
+    fn edge_triggered_registration_stays_armed() {
+        init_test("edge_triggered_registration_stays_armed");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let (sock1, mut sock2) = UnixStream::pair().expect("failed to create unix stream pair");
+        let token = Token::new(2);
+        reactor
+            .register(&sock1, token, Interest::READABLE | Interest::EDGE_TRIGGERED)
+            .expect("register failed");
+        sock2.write_all(b"x").expect("write failed");
+
+        let mut events = Events::with_capacity(8);
+        for _ in 0..2 {
+            reactor
+                .poll(&mut events, Some(Duration::from_millis(100)))
+                .expect("poll failed");
+            let fired = has_readable(&events, token);
+            crate::assert_with_log!(fired, "level delivery while ready", true, fired);
+        }
+        crate::test_complete!("edge_triggered_registration_stays_armed");
+    }
+
+    #[test]
+    fn hangup_wakes_both_directions() {
+        init_test("hangup_wakes_both_directions");
+        let readiness = PollReactor::poll_events_to_interest(
+            libc::POLLHUP,
+            Interest::READABLE | Interest::WRITABLE,
+        );
+        crate::assert_with_log!(
+            readiness.is_readable() && readiness.is_writable() && readiness.is_hup(),
+            "hangup readiness",
+            true,
+            readiness
+        );
+        let masked = PollReactor::poll_events_to_interest(libc::POLLOUT, Interest::READABLE);
+        crate::assert_with_log!(masked.is_empty(), "masked", true, masked);
+        crate::test_complete!("hangup_wakes_both_directions");
+    }
+
+    #[test]
+    fn wake_unblocks_poll() {
+        init_test("wake_unblocks_poll");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let mut events = Events::with_capacity(8);
+
+        let reactor_ref = &reactor;
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(50));
+                reactor_ref.wake().expect("wake failed");
+            });
+
+            let start = Instant::now();
+            let count = reactor
+                .poll(&mut events, Some(Duration::from_secs(5)))
+                .expect("poll failed");
+            let elapsed = start.elapsed();
+            crate::assert_with_log!(
+                elapsed < Duration::from_secs(1),
+                "poll woke early",
+                true,
+                elapsed
+            );
+            crate::assert_with_log!(count == 0, "wake emits no readiness", 0usize, count);
+        });
+        crate::test_complete!("wake_unblocks_poll");
+    }
+
+    #[test]
+    fn wakes_coalesce_and_are_consumed() {
+        init_test("wakes_coalesce_and_are_consumed");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let mut events = Events::with_capacity(8);
+
+        // A wake issued before the poll is not lost.
+        for _ in 0..1_000 {
+            reactor.wake().expect("wake failed");
+        }
+        let start = Instant::now();
+        reactor
+            .poll(&mut events, Some(Duration::from_secs(5)))
+            .expect("poll failed");
+        let early = start.elapsed() < Duration::from_secs(1);
+        crate::assert_with_log!(early, "pending wake returns immediately", true, early);
+
+        // All coalesced wakes were drained by that single poll.
+        let start = Instant::now();
+        reactor
+            .poll(&mut events, Some(Duration::from_millis(50)))
+            .expect("poll failed");
+        let waited = start.elapsed() >= Duration::from_millis(40);
+        crate::assert_with_log!(waited, "no stale wake remains", true, waited);
+
+        // And the reactor still wakes after the drain.
+        reactor.wake().expect("wake failed");
+        let start = Instant::now();
+        reactor
+            .poll(&mut events, Some(Duration::from_secs(5)))
+            .expect("poll failed");
+        let early = start.elapsed() < Duration::from_secs(1);
+        crate::assert_with_log!(early, "wake after drain", true, early);
+        crate::test_complete!("wakes_coalesce_and_are_consumed");
+    }
+
+    #[test]
+    fn wakes_racing_the_drain_are_never_lost() {
+        init_test("wakes_racing_the_drain_are_never_lost");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let done = AtomicBool::new(false);
+
+        // Hammer `wake()` while the poll thread keeps draining, so wakes land
+        // between the drain's reads and its flag update.
+        let (reactor_ref, done_ref) = (&reactor, &done);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..200_000 {
+                    reactor_ref.wake().expect("wake failed");
+                }
+                done_ref.store(true, Ordering::SeqCst);
+            });
+
+            let mut events = Events::with_capacity(8);
+            while !done.load(Ordering::SeqCst) {
+                reactor
+                    .poll(&mut events, Some(Duration::from_millis(1)))
+                    .expect("poll failed");
+            }
+        });
+
+        // A wake left coalesced over an empty pipe would make every later
+        // wake a no-op and this poll run to its timeout.
+        let mut events = Events::with_capacity(8);
+        for round in 0..3 {
+            reactor.wake().expect("wake failed");
+            let start = Instant::now();
+            reactor
+                .poll(&mut events, Some(Duration::from_secs(5)))
+                .expect("poll failed");
+            let early = start.elapsed() < Duration::from_secs(1);
+            crate::assert_with_log!(early, "wake after the race unblocks poll", true, round);
+        }
+        crate::test_complete!("wakes_racing_the_drain_are_never_lost");
+    }
+
+    #[test]
+    fn registration_during_blocked_poll_is_observed() {
+        init_test("registration_during_blocked_poll_is_observed");
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let (sock1, mut sock2) = UnixStream::pair().expect("failed to create unix stream pair");
+        sock2.write_all(b"ready").expect("write failed");
+        let token = Token::new(3);
+
+        let reactor_ref = &reactor;
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(50));
+                reactor_ref
+                    .register(&sock1, token, Interest::READABLE)
+                    .expect("register failed");
+            });
+
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let mut events = Events::with_capacity(8);
+            let mut seen = false;
+            while !seen && Instant::now() < deadline {
+                reactor
+                    .poll(&mut events, Some(Duration::from_secs(5)))
+                    .expect("poll failed");
+                seen = has_readable(&events, token);
+            }
+            crate::assert_with_log!(seen, "late registration observed", true, seen);
+        });
+        crate::test_complete!("registration_during_blocked_poll_is_observed");
+    }
+
+    #[test]
+    fn descriptors_above_fd_setsize_are_supported() {
+        init_test("descriptors_above_fd_setsize_are_supported");
+        let (sock1, mut sock2) = UnixStream::pair().expect("failed to create unix stream pair");
+
+        // Raise the soft descriptor limit far enough to place a descriptor
+        // above FD_SETSIZE, and skip on hosts whose hard limit forbids it.
+        let soft_limit = raise_nofile_limit(4_096);
+        if soft_limit <= 1_500 {
+            eprintln!(
+                "skipping descriptors_above_fd_setsize_are_supported: \
+                 RLIMIT_NOFILE soft limit {soft_limit} cannot reach above FD_SETSIZE"
+            );
+            crate::test_complete!("descriptors_above_fd_setsize_are_supported");
+            return;
+        }
+        // SAFETY: F_DUPFD_CLOEXEC duplicates an open descriptor into a new,
+        // unowned descriptor numbered at least 1500.
+        let high_fd = unsafe { libc::fcntl(sock1.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 1_500) };
+        crate::assert_with_log!(
+            high_fd >= 1_500,
+            "descriptor duplicated above FD_SETSIZE",
+            true,
+            io::Error::last_os_error()
+        );
+        // SAFETY: `high_fd` was just created and is owned by nothing else.
+        let high = unsafe { OwnedFd::from_raw_fd(high_fd) };
+
+        let reactor = PollReactor::new().expect("failed to create reactor");
+        let token = Token::new(1_500);
+        reactor
+            .register(&RawFdSource(high.as_raw_fd()), token, Interest::READABLE)
+            .expect("register failed");
+        sock2.write_all(b"high").expect("write failed");
+
+        let mut events = Events::with_capacity(8);
+        reactor
+            .poll(&mut events, Some(Duration::from_secs(1)))
+            .expect("poll failed");
+        let fired = has_readable(&events, token);
+        crate::assert_with_log!(fired, "fd above FD_SETSIZE reported", true, high_fd);
+        reactor.deregister(token).expect("deregister failed");
+        crate::test_complete!("descriptors_above_fd_setsize_are_supported");
+    }
+
+    /// Documents, rather than asserts, how a wait scales with idle
+    /// registrations compared with the native backend on this host.
+    #[test]
+    fn scalability_smoke() {
+        init_test("scalability_smoke");
+        const ROUNDS: u32 = 50;
+
+        fn time_waits(reactor: &dyn Reactor, rounds: u32) -> Duration {
+            let mut events = Events::with_capacity(64);
+            let start = Instant::now();
+            for _ in 0..rounds {
+                reactor.wake().expect("wake failed");
+                reactor
+                    .poll(&mut events, Some(Duration::from_secs(1)))
+                    .expect("poll failed");
+            }
+            start.elapsed() / rounds
+        }
+
+        // Each registration holds a socket pair; leave headroom for the
+        // reactors and the test harness itself.
+        let soft_limit = raise_nofile_limit(4_096);
+        let max_registrations = usize::try_from(soft_limit.saturating_sub(64) / 2).unwrap_or(0);
+        for registrations in [16usize, 256, 1_024] {
+            if registrations > max_registrations {
+                eprintln!(
+                    "skipping scalability sample of {registrations} registrations: \
+                     RLIMIT_NOFILE soft limit is {soft_limit}"
+                );
+                continue;
+            }
+            let pairs: Vec<_> = (0..registrations)
+                .map(|_| UnixStream::pair().expect("failed to create unix stream pair"))
+                .collect();
+            let poll = PollReactor::new().expect("failed to create reactor");
+            let native = super::super::create_native_reactor().ok();
+            for (index, (sock, _)) in pairs.iter().enumerate() {
+                poll.register(sock, Token::new(index), Interest::READABLE)
+                    .expect("register failed");
+                if let Some(native) = &native {
+                    native
+                        .register(sock, Token::new(index), Interest::READABLE)
+                        .expect("native register failed");
+                }
+            }
+
+            let poll_wait = time_waits(&poll, ROUNDS);
+            let native_wait = native.as_deref().map(|native| time_waits(native, ROUNDS));
+            tracing::info!(
+                registrations,
+                poll_wait_us = poll_wait.as_micros() as u64,
+                native_wait_us = native_wait.map(|wait| wait.as_micros() as u64),
+                native_backend = native.as_deref().map(|native| native.backend().as_str()),
+                "poll reactor scalability sample"
+            );
+        }
+        crate::test_complete!("scalability_smoke");
+    }
+}
@@ -243,6 +243,10 @@ mod iocp_impl {
         fn registration_count(&self) -> usize {
             self.registrations.lock().len()
         }
+
+        fn backend(&self) -> crate::runtime::reactor::ReactorBackend {
+            crate::runtime::reactor::ReactorBackend::Iocp
+        }
     }
 
     impl std::fmt::Debug for IocpReactor {
@@ -0,0 +1,908 @@
+//! Cooperative memory reclamation under pressure.
+//!
+//! Caches and buffers register a [`Reclaimable`] handle with a
+//! [`ReclaimCoordinator`] under a name and a [`ReclaimPriority`]. The
+//! coordinator samples a [`PressureSource`] — an [`InjectedPressure`] value,
+//! region memory-budget accounting via [`MemoryPressure::from_region_budgets`],
+//! or the cgroup memory controller through [`CgroupMemoryPressure`] — and when
+//! usage reaches the high watermark asks registrants to give memory back, in
+//! priority order, until usage would be back at the low watermark. The point
+//! is to shrink caches before the kernel's OOM killer picks a victim.
+//!
+//! # Hysteresis
+//!
+//! A round starts only when usage is at or above
+//! [`ReclaimConfig::high_watermark`] and targets
+//! [`ReclaimConfig::low_watermark`], so a successful round leaves headroom
+//! before the next crossing. Independently, no round starts within
+//! [`ReclaimConfig::cooldown`] of the previous one, even if pressure stays
+//! high: memory freed by a reclaimer may take a while to show up in the
+//! sampled usage, and asking again immediately would only empty caches that
+//! are about to be needed.
+//!
+//! # Timeouts
+//!
+//! Each registrant gets [`ReclaimConfig::per_registrant_timeout`] to answer.
+//! A reclaimer that misses it is recorded as [`ReclaimEvent::TimedOut`] and
+//! skipped; its future is dropped, so reclaimers must be cancel-safe. The
+//! coordinator then asks the next registrant for the remaining target.
+//!
+//! All timing uses the [`Cx`] clock, so under the lab runtime the whole
+//! sequence runs on virtual time and replays identically for every seed.
+//!
+//! # Implementations
+//!
+//! [`ArtifactCache`](super::ArtifactCache) (behind a mutex) evicts with its
+//! configured policy, [`ShardedPool`](super::ShardedPool) shrinks its
+//! free-lists, and [`DecodingPipeline`](crate::decoding::DecodingPipeline)
+//! (behind a mutex) releases symbols held by its reordering buffer to the
+//! decoder.
+
+use crate::cx::Cx;
+use crate::runtime::resource_monitor::RuntimePressureRegionMemoryBudgetSnapshot;
+use crate::time::{sleep, timeout};
+use crate::types::Time;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum number of events kept by a [`ReclaimCoordinator`].
+pub const MAX_RECLAIM_EVENTS: usize = 1024;
+
+/// cgroup v1 reports an unlimited group as a huge page-aligned limit.
+const CGROUP_V1_UNLIMITED: u64 = 1 << 62;
+
+/// Future returned by [`Reclaimable::reclaim`].
+pub type ReclaimFuture<'a> = Pin<Box<dyn Future<Output = u64> + Send + 'a>>;
+
+/// A component that can give memory back on request.
+pub trait Reclaimable: Send + Sync {
+    /// Releases roughly `target_bytes` and resolves to the bytes actually
+    /// released, which may be more or less than asked for.
+    ///
+    /// The future may be dropped before it completes when it exceeds the
+    /// coordinator's per-registrant timeout.
+    fn reclaim(&self, target_bytes: u64) -> ReclaimFuture<'_>;
+}
+
+/// Order in which registrants are asked to reclaim; lower classes go first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReclaimPriority {
+    /// Derived data that is cheap to rebuild, such as prefetched or memoized
+    /// results.
+    Speculative,
+    /// Caches whose misses cost a recomputation or a round trip.
+    Cache,
+    /// Buffers whose release adds latency or work downstream.
+    Buffer,
+    /// State that is expensive to give up; asked last.
+    Essential,
+}
+
+impl ReclaimPriority {
+    /// Stable name for logs and events.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Speculative => "speculative",
+            Self::Cache => "cache",
+            Self::Buffer => "buffer",
+            Self::Essential => "essential",
+        }
+    }
+}
+
+/// One memory usage sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryPressure {
+    /// Bytes in use.
+    pub used_bytes: u64,
+    /// Bytes available before the limit is hit.
+    pub limit_bytes: u64,
+}
+
+impl MemoryPressure {
+    /// Creates a sample.
+    #[must_use]
+    pub const fn new(used_bytes: u64, limit_bytes: u64) -> Self {
+        Self {
+            used_bytes,
+            limit_bytes,
+        }
+    }
+
+    /// Sums observed usage and declared budgets across region budget rows.
+    #[must_use]
+    pub fn from_region_budgets(rows: &[RuntimePressureRegionMemoryBudgetSnapshot]) -> Self {
+        rows.iter().fold(Self::new(0, 0), |total, row| {
+            Self::new(
+                total.used_bytes.saturating_add(row.observed_memory_bytes),
+                total
+                    .limit_bytes
+                    .saturating_add(row.declared_memory_budget_bytes),
+            )
+        })
+    }
+
+    /// Usage as a fraction of the limit; any usage against a zero limit is
+    /// infinite pressure.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(self) -> f64 {
+        if self.limit_bytes == 0 {
+            return if self.used_bytes == 0 { 0.0 } else { f64::INFINITY };
+        }
+        self.used_bytes as f64 / self.limit_bytes as f64
+    }
+
+    /// Bytes that must be released to bring usage down to `ratio` of the
+    /// limit.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+    pub fn bytes_above(self, ratio: f64) -> u64 {
+        let allowed = (self.limit_bytes as f64 * ratio.max(0.0)) as u64;
+        self.used_bytes.saturating_sub(allowed)
+    }
+}
+
+/// Where the coordinator reads memory usage from.
+pub trait PressureSource: Send + Sync {
+    /// Current usage, or `None` when the source is unavailable.
+    fn sample(&self) -> Option<MemoryPressure>;
+}
+
+impl<F> PressureSource for F
+where
+    F: Fn() -> Option<MemoryPressure> + Send + Sync,
+{
+    fn sample(&self) -> Option<MemoryPressure> {
+        self()
+    }
+}
+
+/// Pressure set explicitly, for tests and for embedders with their own
+/// accounting.
+#[derive(Debug, Default)]
+pub struct InjectedPressure {
+    current: Mutex<Option<MemoryPressure>>,
+}
+
+impl InjectedPressure {
+    /// Creates a source that reports nothing until [`set`](Self::set).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports `used_bytes` of `limit_bytes` from now on.
+    pub fn set(&self, used_bytes: u64, limit_bytes: u64) {
+        *self.current.lock() = Some(MemoryPressure::new(used_bytes, limit_bytes));
+    }
+
+    /// Stops reporting.
+    pub fn clear(&self) {
+        *self.current.lock() = None;
+    }
+}
+
+impl PressureSource for InjectedPressure {
+    fn sample(&self) -> Option<MemoryPressure> {
+        *self.current.lock()
+    }
+}
+
+/// Usage and limit of the process's memory cgroup on Linux.
+///
+/// Reads `memory.current` and `memory.max` (cgroup v2), falling back to the
+/// v1 memory controller. Unlimited groups and other platforms report `None`.
+#[derive(Debug, Clone)]
+pub struct CgroupMemoryPressure {
+    root: PathBuf,
+}
+
+impl Default for CgroupMemoryPressure {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CgroupMemoryPressure {
+    /// Reads the cgroup mounted at `/sys/fs/cgroup`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_root("/sys/fs/cgroup")
+    }
+
+    /// Reads the cgroup mounted at `root`.
+    #[must_use]
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn read_u64(&self, file: &str) -> Option<u64> {
+        std::fs::read_to_string(self.root.join(file))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+}
+
+impl PressureSource for CgroupMemoryPressure {
+    fn sample(&self) -> Option<MemoryPressure> {
+        if !cfg!(target_os = "linux") {
+            return None;
+        }
+        // `memory.max` reads "max" for an unlimited group, which fails to parse.
+        if let Some(used) = self.read_u64("memory.current") {
+            let limit = self.read_u64("memory.max")?;
+            return Some(MemoryPressure::new(used, limit));
+        }
+        let used = self.read_u64("memory/memory.usage_in_bytes")?;
+        let limit = self.read_u64("memory/memory.limit_in_bytes")?;
+        (limit < CGROUP_V1_UNLIMITED).then(|| MemoryPressure::new(used, limit))
+    }
+}
+
+/// Thresholds and pacing for a [`ReclaimCoordinator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReclaimConfig {
+    /// Usage ratio at or above which a round starts.
+    pub high_watermark: f64,
+    /// Usage ratio a round tries to get back to; clamped to `high_watermark`.
+    pub low_watermark: f64,
+    /// Time each registrant gets before it is skipped.
+    pub per_registrant_timeout: Duration,
+    /// Minimum time between the starts of two pressure-triggered rounds.
+    pub cooldown: Duration,
+}
+
+impl Default for ReclaimConfig {
+    fn default() -> Self {
+        Self {
+            high_watermark: 0.90,
+            low_watermark: 0.80,
+            per_registrant_timeout: Duration::from_millis(100),
+            cooldown: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Handle returned by [`ReclaimCoordinator::register`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReclaimId(u64);
+
+/// Structured record of what a reclaim round did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReclaimEvent {
+    /// A round started.
+    RoundStarted {
+        /// When the round started.
+        at: Time,
+        /// The sample that triggered it; `None` for a forced round.
+        pressure: Option<MemoryPressure>,
+        /// Bytes the round tries to release.
+        target_bytes: u64,
+    },
+    /// A registrant answered in time.
+    Reclaimed {
+        /// When it answered.
+        at: Time,
+        /// Registrant name.
+        name: String,
+        /// Registrant priority.
+        priority: ReclaimPriority,
+        /// Bytes it was asked for.
+        requested_bytes: u64,
+        /// Bytes it reported releasing.
+        reclaimed_bytes: u64,
+    },
+    /// A registrant missed its timeout and was skipped.
+    TimedOut {
+        /// When it was given up on.
+        at: Time,
+        /// Registrant name.
+        name: String,
+        /// Registrant priority.
+        priority: ReclaimPriority,
+        /// Bytes it was asked for.
+        requested_bytes: u64,
+    },
+    /// A round finished.
+    RoundFinished {
+        /// When the round finished.
+        at: Time,
+        /// Bytes the round tried to release.
+        target_bytes: u64,
+        /// Bytes registrants reported releasing.
+        reclaimed_bytes: u64,
+    },
+}
+
+/// Summary of one reclaim round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReclaimRound {
+    /// When the round started.
+    pub started: Time,
+    /// The sample that triggered it; `None` for a forced round.
+    pub pressure: Option<MemoryPressure>,
+    /// Bytes the round tried to release.
+    pub target_bytes: u64,
+    /// Bytes registrants reported releasing.
+    pub reclaimed_bytes: u64,
+    /// Registrants skipped after missing their timeout.
+    pub timed_out: Vec<String>,
+}
+
+impl ReclaimRound {
+    /// Returns true if registrants released at least the target.
+    #[must_use]
+    pub const fn satisfied(&self) -> bool {
+        self.reclaimed_bytes >= self.target_bytes
+    }
+}
+
+#[derive(Clone)]
+struct Registrant {
+    id: ReclaimId,
+    name: String,
+    priority: ReclaimPriority,
+    handle: Arc<dyn Reclaimable>,
+}
+
+#[derive(Default)]
+struct CoordinatorState {
+    next_id: u64,
+    registrants: Vec<Registrant>,
+    last_round: Option<Time>,
+    events: VecDeque<ReclaimEvent>,
+}
+
+/// Asks registered [`Reclaimable`]s to release memory when a
+/// [`PressureSource`] reports usage above the high watermark.
+///
+/// Call [`check`](Self::check) periodically, or spawn [`run`](Self::run).
+pub struct ReclaimCoordinator {
+    config: ReclaimConfig,
+    source: Arc<dyn PressureSource>,
+    state: Mutex<CoordinatorState>,
+}
+
+impl fmt::Debug for ReclaimCoordinator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("ReclaimCoordinator")
+            .field("config", &self.config)
+            .field("registrants", &state.registrants.len())
+            .field("last_round", &state.last_round)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReclaimCoordinator {
+    /// Creates a coordinator sampling `source`.
+    #[must_use]
+    pub fn new(config: ReclaimConfig, source: Arc<dyn PressureSource>) -> Self {
+        let config = ReclaimConfig {
+            low_watermark: config.low_watermark.min(config.high_watermark),
+            ..config
+        };
+        Self {
+            config,
+            source,
+            state: Mutex::new(CoordinatorState::default()),
+        }
+    }
+
+    /// Returns the effective configuration.
+    #[must_use]
+    pub const fn config(&self) -> &ReclaimConfig {
+        &self.config
+    }
+
+    /// Registers `handle`. Within a priority class, registrants are asked in
+    /// registration order.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        priority: ReclaimPriority,
+        handle: Arc<dyn Reclaimable>,
+    ) -> ReclaimId {
+        let mut state = self.state.lock();
+        let id = ReclaimId(state.next_id);
+        state.next_id += 1;
+        state.registrants.push(Registrant {
+            id,
+            name: name.into(),
+            priority,
+            handle,
+        });
+        id
+    }
+
+    /// Removes a registrant. Returns false if it was not registered.
+    pub fn unregister(&self, id: ReclaimId) -> bool {
+        let mut state = self.state.lock();
+        let before = state.registrants.len();
+        state.registrants.retain(|registrant| registrant.id != id);
+        state.registrants.len() != before
+    }
+
+    /// Number of registrants.
+    #[must_use]
+    pub fn registrants(&self) -> usize {
+        self.state.lock().registrants.len()
+    }
+
+    /// Samples the pressure source.
+    #[must_use]
+    pub fn sample(&self) -> Option<MemoryPressure> {
+        self.source.sample()
+    }
+
+    /// Events recorded so far, oldest first. At most
+    /// [`MAX_RECLAIM_EVENTS`] are kept.
+    #[must_use]
+    pub fn events(&self) -> Vec<ReclaimEvent> {
+        self.state.lock().events.iter().cloned().collect()
+    }
+
+    /// Removes and returns the recorded events.
+    pub fn take_events(&self) -> Vec<ReclaimEvent> {
+        self.state.lock().events.drain(..).collect()
+    }
+
+    /// Samples pressure and runs a round if usage is at or above the high
+    /// watermark and the cooldown has elapsed.
+    pub async fn check(&self, cx: &Cx) -> Option<ReclaimRound> {
+        let pressure = self.source.sample()?;
+        if pressure.ratio() < self.config.high_watermark {
+            return None;
+        }
+        let now = cx.now();
+        {
+            let mut state = self.state.lock();
+            let cooldown = duration_nanos(self.config.cooldown);
+            if state
+                .last_round
+                .is_some_and(|last| now.duration_since(last) < cooldown)
+            {
+                return None;
+            }
+            state.last_round = Some(now);
+        }
+        let target_bytes = pressure.bytes_above(self.config.low_watermark);
+        Some(self.run_round(cx, Some(pressure), target_bytes).await)
+    }
+
+    /// Runs a round for `target_bytes` regardless of pressure. The round
+    /// restarts the cooldown.
+    pub async fn reclaim(&self, cx: &Cx, target_bytes: u64) -> ReclaimRound {
+        self.state.lock().last_round = Some(cx.now());
+        self.run_round(cx, None, target_bytes).await
+    }
+
+    /// Calls [`check`](Self::check) every `interval` until `cx` is cancelled.
+    pub async fn run(&self, cx: &Cx, interval: Duration) {
+        while cx.checkpoint().is_ok() {
+            let _ = self.check(cx).await;
+            sleep(cx.now(), interval).await;
+        }
+    }
+
+    async fn run_round(
+        &self,
+        cx: &Cx,
+        pressure: Option<MemoryPressure>,
+        target_bytes: u64,
+    ) -> ReclaimRound {
+        let started = cx.now();
+        let mut registrants = self.state.lock().registrants.clone();
+        registrants.sort_by_key(|registrant| (registrant.priority, registrant.id));
+        self.record(ReclaimEvent::RoundStarted {
+            at: started,
+            pressure,
+            target_bytes,
+        });
+
+        let mut reclaimed_bytes = 0_u64;
+        let mut timed_out = Vec::new();
+        for registrant in registrants {
+            if reclaimed_bytes >= target_bytes {
+                break;
+            }
+            let Registrant {
+                name,
+                priority,
+                handle,
+                ..
+            } = registrant;
+            let requested_bytes = target_bytes - reclaimed_bytes;
+            let answer = timeout(
+                cx.now(),
+                self.config.per_registrant_timeout,
+                handle.reclaim(requested_bytes),
+            )
+            .await;
+            let at = cx.now();
+            if let Ok(released) = answer {
+                reclaimed_bytes = reclaimed_bytes.saturating_add(released);
+                self.record(ReclaimEvent::Reclaimed {
+                    at,
+                    name,
+                    priority,
+                    requested_bytes,
+                    reclaimed_bytes: released,
+                });
+            } else {
+                timed_out.push(name.clone());
+                self.record(ReclaimEvent::TimedOut {
+                    at,
+                    name,
+                    priority,
+                    requested_bytes,
+                });
+            }
+        }
+
+        self.record(ReclaimEvent::RoundFinished {
+            at: cx.now(),
+            target_bytes,
+            reclaimed_bytes,
+        });
+        ReclaimRound {
+            started,
+            pressure,
+            target_bytes,
+            reclaimed_bytes,
+            timed_out,
+        }
+    }
+
+    fn record(&self, event: ReclaimEvent) {
+        crate::tracing_compat::info!(?event, "memory reclaim");
+        let mut state = self.state.lock();
+        if state.events.len() == MAX_RECLAIM_EVENTS {
+            state.events.pop_front();
+        }
+        state.events.push_back(event);
+    }
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::LabRuntime;
+    use crate::types::Budget;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// How a [`Scripted`] reclaimer answers.
+    #[derive(Debug, Clone, Copy)]
+    enum Behavior {
+        Immediate,
+        After(Duration),
+        Stuck,
+    }
+
+    /// Reclaimer holding `available` bytes that logs every request.
+    #[derive(Debug)]
+    struct Scripted {
+        name: &'static str,
+        available: Mutex<u64>,
+        behavior: Behavior,
+        calls: Arc<Mutex<Vec<(&'static str, u64)>>>,
+    }
+
+    impl Scripted {
+        fn new(
+            name: &'static str,
+            available: u64,
+            behavior: Behavior,
+            calls: &Arc<Mutex<Vec<(&'static str, u64)>>>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                available: Mutex::new(available),
+                behavior,
+                calls: Arc::clone(calls),
+            })
+        }
+    }
+
+    impl Reclaimable for Scripted {
+        fn reclaim(&self, target_bytes: u64) -> ReclaimFuture<'_> {
+            self.calls.lock().push((self.name, target_bytes));
+            Box::pin(async move {
+                match self.behavior {
+                    Behavior::Immediate => {}
+                    Behavior::After(delay) => {
+                        let now = Cx::current().expect("lab cx").now();
+                        sleep(now, delay).await;
+                    }
+                    Behavior::Stuck => std::future::pending::<()>().await,
+                }
+                let mut available = self.available.lock();
+                let released = (*available).min(target_bytes);
+                *available -= released;
+                released
+            })
+        }
+    }
+
+    fn config() -> ReclaimConfig {
+        ReclaimConfig {
+            high_watermark: 0.9,
+            low_watermark: 0.8,
+            per_registrant_timeout: Duration::from_millis(50),
+            cooldown: Duration::from_secs(1),
+        }
+    }
+
+    /// Runs `scenario` to completion on a lab runtime seeded with `seed`.
+    fn run_lab<F, Fut>(seed: u64, scenario: F)
+    where
+        F: FnOnce(Cx) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut runtime = LabRuntime::with_seed(seed);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let (task, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("lab cx");
+                scenario(cx).await;
+            })
+            .expect("create scenario task");
+        runtime.scheduler.lock().schedule(task, 0);
+        let _report = runtime.run_with_auto_advance();
+    }
+
+    #[test]
+    fn reclaims_in_priority_order_until_target_met() {
+        init_test("reclaims_in_priority_order_until_target_met");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pressure = Arc::new(InjectedPressure::new());
+        let coordinator = Arc::new(ReclaimCoordinator::new(config(), pressure.clone()));
+        let essential = Scripted::new("essential", 500, Behavior::Immediate, &calls);
+        let buffer = Scripted::new("buffer", 500, Behavior::Immediate, &calls);
+        let cache = Scripted::new("cache", 100, Behavior::Immediate, &calls);
+        coordinator.register("essential", ReclaimPriority::Essential, essential);
+        coordinator.register("buffer", ReclaimPriority::Buffer, buffer);
+        coordinator.register("cache", ReclaimPriority::Cache, cache);
+        pressure.set(950, 1000);
+
+        let rounds = Arc::new(Mutex::new(Vec::new()));
+        let (driver, log) = (Arc::clone(&coordinator), Arc::clone(&rounds));
+        run_lab(1, move |cx| async move {
+            let round = driver.check(&cx).await;
+            log.lock().push(round);
+        });
+
+        let round = rounds.lock()[0].clone().expect("round above high watermark");
+        crate::assert_with_log!(round.target_bytes == 150, "target", 150, round.target_bytes);
+        crate::assert_with_log!(round.satisfied(), "satisfied", true, round.reclaimed_bytes);
+        let calls = calls.lock().clone();
+        let expected = vec![("cache", 150), ("buffer", 50)];
+        crate::assert_with_log!(calls == expected, "priority order", expected, calls);
+        crate::test_complete!("reclaims_in_priority_order_until_target_met");
+    }
+
+    #[test]
+    fn partial_reclaims_accumulate_across_registrants() {
+        init_test("partial_reclaims_accumulate_across_registrants");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pressure = Arc::new(InjectedPressure::new());
+        let coordinator = Arc::new(ReclaimCoordinator::new(config(), pressure.clone()));
+        for name in ["a", "b", "c"] {
+            let handle = Scripted::new(name, 40, Behavior::Immediate, &calls);
+            coordinator.register(name, ReclaimPriority::Cache, handle);
+        }
+        pressure.set(1000, 1000);
+
+        let rounds = Arc::new(Mutex::new(Vec::new()));
+        let (driver, log) = (Arc::clone(&coordinator), Arc::clone(&rounds));
+        run_lab(2, move |cx| async move {
+            let round = driver.check(&cx).await;
+            log.lock().push(round);
+        });
+
+        let round = rounds.lock()[0].clone().expect("round at full usage");
+        let reclaimed = round.reclaimed_bytes;
+        crate::assert_with_log!(reclaimed == 120, "accumulated", 120, reclaimed);
+        crate::assert_with_log!(!round.satisfied(), "short of target", 200, round.target_bytes);
+        let calls = calls.lock().clone();
+        let expected = vec![("a", 200), ("b", 160), ("c", 120)];
+        crate::assert_with_log!(calls == expected, "remaining target", expected, calls);
+        let finished = coordinator.events().last().cloned();
+        let expected_event = ReclaimEvent::RoundFinished {
+            at: Time::ZERO,
+            target_bytes: 200,
+            reclaimed_bytes: 120,
+        };
+        crate::assert_with_log!(
+            finished == Some(expected_event.clone()),
+            "round finished event",
+            Some(expected_event),
+            finished
+        );
+        crate::test_complete!("partial_reclaims_accumulate_across_registrants");
+    }
+
+    #[test]
+    fn stuck_reclaimer_times_out_and_is_skipped() {
+        init_test("stuck_reclaimer_times_out_and_is_skipped");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pressure = Arc::new(InjectedPressure::new());
+        let coordinator = Arc::new(ReclaimCoordinator::new(config(), pressure.clone()));
+        let stuck = Scripted::new("stuck", 1000, Behavior::Stuck, &calls);
+        let healthy = Scripted::new("healthy", 1000, Behavior::Immediate, &calls);
+        coordinator.register("stuck", ReclaimPriority::Speculative, stuck);
+        coordinator.register("healthy", ReclaimPriority::Cache, healthy);
+        pressure.set(900, 1000);
+
+        let rounds = Arc::new(Mutex::new(Vec::new()));
+        let (driver, log) = (Arc::clone(&coordinator), Arc::clone(&rounds));
+        run_lab(3, move |cx| async move {
+            let round = driver.check(&cx).await;
+            log.lock().push(round);
+        });
+
+        let round = rounds.lock()[0].clone().expect("round at high watermark");
+        let reclaimed = round.reclaimed_bytes;
+        crate::assert_with_log!(round.satisfied(), "healthy covers target", 100, reclaimed);
+        crate::assert_with_log!(
+            round.timed_out == vec!["stuck".to_string()],
+            "stuck skipped",
+            vec!["stuck"],
+            round.timed_out
+        );
+        let events = coordinator.events();
+        let timed_out = ReclaimEvent::TimedOut {
+            at: Time::from_millis(50),
+            name: "stuck".to_string(),
+            priority: ReclaimPriority::Speculative,
+            requested_bytes: 100,
+        };
+        let reclaimed = ReclaimEvent::Reclaimed {
+            at: Time::from_millis(50),
+            name: "healthy".to_string(),
+            priority: ReclaimPriority::Cache,
+            requested_bytes: 100,
+            reclaimed_bytes: 100,
+        };
+        crate::assert_with_log!(events[1] == timed_out, "timeout event", timed_out, events[1]);
+        crate::assert_with_log!(events[2] == reclaimed, "next registrant", reclaimed, events[2]);
+        crate::test_complete!("stuck_reclaimer_times_out_and_is_skipped");
+    }
+
+    #[test]
+    fn cooldown_prevents_repeated_rounds() {
+        init_test("cooldown_prevents_repeated_rounds");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pressure = Arc::new(InjectedPressure::new());
+        let coordinator = Arc::new(ReclaimCoordinator::new(config(), pressure.clone()));
+        let cache = Scripted::new("cache", 10, Behavior::Immediate, &calls);
+        coordinator.register("cache", ReclaimPriority::Cache, cache);
+        pressure.set(950, 1000);
+
+        let rounds = Arc::new(Mutex::new(Vec::new()));
+        let (driver, log) = (Arc::clone(&coordinator), Arc::clone(&rounds));
+        run_lab(4, move |cx| async move {
+            for delay in [0, 500, 499, 1] {
+                sleep(cx.now(), Duration::from_millis(delay)).await;
+                let round = driver.check(&cx).await;
+                log.lock().push(round.map(|round| round.started));
+            }
+        });
+
+        let rounds = rounds.lock().clone();
+        let expected = vec![Some(Time::ZERO), None, None, Some(Time::from_millis(1000))];
+        crate::assert_with_log!(rounds == expected, "cooldown", expected, rounds);
+        let calls = calls.lock().len();
+        crate::assert_with_log!(calls == 2, "reclaim calls", 2, calls);
+        crate::test_complete!("cooldown_prevents_repeated_rounds");
+    }
+
+    /// Drives pressure up and down through a coordinator with slow, stuck
+    /// and fast registrants, returning the recorded events.
+    fn pressure_scenario(seed: u64) -> Vec<ReclaimEvent> {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let pressure = Arc::new(InjectedPressure::new());
+        let coordinator = Arc::new(ReclaimCoordinator::new(config(), pressure.clone()));
+        let handles = [
+            ("memo", 60, Behavior::After(Duration::from_millis(7))),
+            ("hung", 500, Behavior::Stuck),
+            ("spill", 500, Behavior::After(Duration::from_millis(3))),
+            ("state", 500, Behavior::Immediate),
+        ];
+        let priorities = [
+            ReclaimPriority::Speculative,
+            ReclaimPriority::Cache,
+            ReclaimPriority::Buffer,
+            ReclaimPriority::Essential,
+        ];
+        for ((name, available, behavior), priority) in handles.into_iter().zip(priorities) {
+            let handle = Scripted::new(name, available, behavior, &calls);
+            coordinator.register(name, priority, handle);
+        }
+
+        let driver = Arc::clone(&coordinator);
+        run_lab(seed, move |cx| async move {
+            for used in [500, 960, 930, 700, 990] {
+                pressure.set(used, 1000);
+                let _ = driver.check(&cx).await;
+                sleep(cx.now(), Duration::from_millis(600)).await;
+            }
+        });
+        coordinator.take_events()
+    }
+
+    #[test]
+    fn lab_drives_reclaim_sequence_deterministically() {
+        init_test("lab_drives_reclaim_sequence_deterministically");
+        let events = pressure_scenario(11);
+        let again = pressure_scenario(0xdead_beef);
+        crate::assert_with_log!(events == again, "seed-independent", events, again);
+
+        let rounds: Vec<(Time, u64)> = events
+            .iter()
+            .filter_map(|event| match event {
+                ReclaimEvent::RoundStarted {
+                    at, target_bytes, ..
+                } => Some((*at, *target_bytes)),
+                _ => None,
+            })
+            .collect();
+        // 960 at 600ms starts a round; 930 falls inside its cooldown; 700 is
+        // below the high watermark; 990 starts the second round.
+        let expected = vec![
+            (Time::from_millis(600), 160),
+            (Time::from_millis(2460), 190),
+        ];
+        crate::assert_with_log!(rounds == expected, "rounds", expected, rounds);
+        let timeouts = events
+            .iter()
+            .filter(|event| matches!(event, ReclaimEvent::TimedOut { .. }))
+            .count();
+        crate::assert_with_log!(timeouts == 2, "hung skipped each round", 2, timeouts);
+        crate::test_complete!("lab_drives_reclaim_sequence_deterministically");
+    }
+
+    #[test]
+    fn cgroup_source_reads_v2_and_ignores_unlimited() {
+        init_test("cgroup_source_reads_v2_and_ignores_unlimited");
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("memory.current"), "750\n").expect("write current");
+        std::fs::write(dir.path().join("memory.max"), "1000\n").expect("write max");
+        let source = CgroupMemoryPressure::with_root(dir.path());
+        let expected = cfg!(target_os = "linux").then(|| MemoryPressure::new(750, 1000));
+        let sample = source.sample();
+        crate::assert_with_log!(sample == expected, "v2 sample", expected, sample);
+
+        std::fs::write(dir.path().join("memory.max"), "max\n").expect("write unlimited");
+        let sample = source.sample();
+        crate::assert_with_log!(sample.is_none(), "unlimited", None::<MemoryPressure>, sample);
+        crate::test_complete!("cgroup_source_reads_v2_and_ignores_unlimited");
+    }
+}
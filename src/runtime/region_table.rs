@@ -172,6 +172,13 @@ impl RegionTable {
         self.regions.get_mut(index)
     }
 
+    /// Returns the generation of the record occupying arena slot `slot`.
+    #[inline]
+    #[must_use]
+    pub(crate) fn occupant_generation(&self, slot: u32) -> Option<u32> {
+        self.regions.occupant_generation(slot)
+    }
+
     /// Inserts a new region record into the arena.
     #[inline]
     pub fn insert(&mut self, mut record: RegionRecord) -> ArenaIndex {
@@ -312,6 +319,7 @@ impl RegionTable {
             .ok_or(RegionCreateError::ParentNotFound(parent))?;
         let parent_budget = parent_record.budget();
         let parent_capability_budget = parent_record.capability_budget();
+        let parent_subtree = parent_record.subtree_task_counter();
 
         let effective_budget = parent_budget.meet(budget);
         let effective_capability_budget = parent_capability_budget
@@ -326,6 +334,7 @@ impl RegionTable {
                 now,
                 effective_capability_budget,
             )
+            .with_parent_subtree(parent_subtree)
         });
         let id = RegionId::from_arena(idx);
 
@@ -5293,7 +5293,7 @@ fn derive_thresholds(max_limit: u64, soft_pct: u64, hard_pct: u64) -> (u64, u64)
 /// `ErrorKind::Unsupported` so the caller's `if let Ok(..)` skip in
 /// [`SystemResourceCollector::collect_now`] gracefully omits the
 /// measurement and existing pressure values are preserved.
-mod platform {
+pub(crate) mod platform {
     /// Total system memory or process address-space ceiling, in bytes.
     /// Falls back to a large finite value (16 GiB) when the platform
     /// reports `RLIM_INFINITY` so downstream `usage_ratio()` arithmetic
@@ -31,6 +31,7 @@ pub mod priority_inversion_metamorphic;
 pub mod priority_inversion_oracle;
 #[cfg(test)]
 pub mod ready_dispatch_invariance_metamorphic;
+pub mod region_fair;
 #[cfg(test)]
 pub mod shutdown_behavior_audit_test;
 /// Trait abstraction over runtime backing-state shapes.
@@ -77,6 +78,7 @@ pub use priority_inversion_oracle::{
     InversionId, InversionImpact, InversionOracleConfig, InversionSeverity, InversionStats,
     InversionType, Priority, PriorityInversion, PriorityInversionOracle, ResourceId,
 };
+pub use region_fair::{DEFAULT_REGION_WEIGHT, RegionFairQueue, RegionFairness, RegionVruntime};
 pub use stream_priority::{
     SchedulerIntegration, SchedulerStats, StreamAssignment, StreamPriority, StreamPriorityScheduler,
 };
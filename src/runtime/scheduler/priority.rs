@@ -834,6 +834,35 @@ impl Scheduler {
         }
     }
 
+    /// Raises the priority of a task waiting in the ready lane.
+    ///
+    /// Returns true if `task` was queued there below `priority`. Tasks in the
+    /// cancel or timed lanes are left alone: the cancel lane already runs
+    /// first, and timed tasks are not runnable yet. O(n) rebuild of the ready
+    /// lane, like [`remove`](Self::remove); used for priority inheritance.
+    pub fn promote_ready(&mut self, task: TaskId, priority: u8) -> bool {
+        if !self.ready_entry_is_stealable(task) {
+            return false;
+        }
+        let current = self
+            .ready_lane
+            .iter()
+            .filter(|entry| entry.task == task)
+            .map(|entry| entry.priority)
+            .max();
+        if current.is_none_or(|current| current >= priority) {
+            return false;
+        }
+        self.ready_lane.retain(|entry| entry.task != task);
+        let generation = self.next_gen();
+        self.ready_lane.push(SchedulerEntry {
+            task,
+            priority,
+            generation,
+        });
+        true
+    }
+
     /// Moves a task to the cancel lane (highest priority).
     ///
     /// If the task is not currently scheduled, it will be added to the cancel lane.
@@ -2894,6 +2923,32 @@ mod tests {
         crate::test_complete!("metamorphic_concurrent_cancel_requests_preserve_total_order");
     }
 
+    #[test]
+    fn promote_ready_raises_queued_task_only() {
+        init_test("promote_ready_raises_queued_task_only");
+        let mut sched = Scheduler::new();
+
+        sched.schedule(task(1), 10);
+        sched.schedule(task(2), 100);
+        sched.schedule_cancel(task(3), 50);
+
+        let lowered = sched.promote_ready(task(2), 50);
+        crate::assert_with_log!(!lowered, "never lowers", false, lowered);
+        let cancel = sched.promote_ready(task(3), 250);
+        crate::assert_with_log!(!cancel, "cancel lane untouched", false, cancel);
+        let missing = sched.promote_ready(task(9), 250);
+        crate::assert_with_log!(!missing, "unscheduled task ignored", false, missing);
+        let promoted = sched.promote_ready(task(1), 200);
+        crate::assert_with_log!(promoted, "promoted", true, promoted);
+
+        let len = sched.len();
+        crate::assert_with_log!(len == 3, "len unchanged", 3, len);
+        let order: Vec<_> = std::iter::from_fn(|| sched.pop()).collect();
+        let expected = vec![task(3), task(1), task(2)];
+        crate::assert_with_log!(order == expected, "pop order", expected, order);
+        crate::test_complete!("promote_ready_raises_queued_task_only");
+    }
+
     // ---- Remove from specific lane doesn't corrupt other lanes ----------
 
     #[test]
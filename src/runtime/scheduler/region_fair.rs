@@ -0,0 +1,401 @@
+//! Region-fair ready queue.
+//!
+//! The ready lane is task-fair by default: every ready task competes on its
+//! own, so a region with a thousand ready tasks receives a thousand times the
+//! dispatches of a region with one. [`RegionFairQueue`] instead shares
+//! dispatches between *regions* in proportion to their weights.
+//!
+//! Each region accumulates a virtual runtime: every dispatch of one of its
+//! tasks charges `VRUNTIME_QUANTUM / weight`, so a heavier region is charged
+//! less per dispatch and is picked more often. The queue always dispatches
+//! from the non-empty region with the smallest virtual runtime (ties go to
+//! the smaller [`RegionId`]), and falls back to ordinary task-fair ordering
+//! among that region's ready tasks.
+//!
+//! A region that goes idle and later becomes ready again resumes at the
+//! queue's minimum virtual runtime rather than its stale value, so idleness
+//! cannot be banked into a burst that starves everyone else.
+//!
+//! Every decision is a function of the push/pop sequence and the RNG hints
+//! supplied by the caller, so the queue is deterministic under the lab
+//! runtime. The production scheduler shares one queue between its workers
+//! (see [`ThreeLaneScheduler::set_region_fairness`]).
+//!
+//! [`ThreeLaneScheduler::set_region_fairness`]: super::ThreeLaneScheduler::set_region_fairness
+
+use super::priority::Scheduler;
+use crate::types::{RegionId, TaskId};
+use std::collections::BTreeMap;
+
+/// Weight given to regions that have not been assigned one explicitly.
+pub const DEFAULT_REGION_WEIGHT: u32 = 1024;
+
+/// Virtual runtime charged per dispatch to a region of weight 1.
+const VRUNTIME_QUANTUM: u64 = DEFAULT_REGION_WEIGHT as u64 * 1024;
+
+/// How the ready lane shares dispatches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RegionFairness {
+    /// Every ready task competes individually (the historical behavior).
+    #[default]
+    TaskFair,
+    /// Ready dispatches are shared between regions by weighted virtual
+    /// runtime, then task-fair within the chosen region.
+    RegionFair,
+}
+
+impl RegionFairness {
+    /// Stable name used in traces and reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::TaskFair => "task_fair",
+            Self::RegionFair => "region_fair",
+        }
+    }
+
+    /// Returns true for [`RegionFairness::RegionFair`].
+    #[must_use]
+    pub const fn is_region_fair(self) -> bool {
+        matches!(self, Self::RegionFair)
+    }
+}
+
+impl std::fmt::Display for RegionFairness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Point-in-time fairness accounting for one region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionVruntime {
+    /// The region.
+    pub region: RegionId,
+    /// Current weight.
+    pub weight: u32,
+    /// Accumulated virtual runtime.
+    pub vruntime: u64,
+    /// Ready-lane dispatches charged to the region.
+    pub dispatched: u64,
+    /// Tasks currently queued for the region.
+    pub ready: usize,
+}
+
+#[derive(Debug)]
+struct RegionLane {
+    weight: u32,
+    vruntime: u64,
+    dispatched: u64,
+    ready: Scheduler,
+}
+
+impl RegionLane {
+    fn new(weight: u32, vruntime: u64) -> Self {
+        Self {
+            weight,
+            vruntime,
+            dispatched: 0,
+            ready: Scheduler::with_capacity(8),
+        }
+    }
+
+    fn charge(&mut self) {
+        let cost = (VRUNTIME_QUANTUM / u64::from(self.weight)).max(1);
+        self.vruntime = self.vruntime.saturating_add(cost);
+        self.dispatched = self.dispatched.saturating_add(1);
+    }
+}
+
+/// Ready queue that shares dispatches between regions by weighted virtual
+/// runtime.
+#[derive(Debug)]
+pub struct RegionFairQueue {
+    lanes: BTreeMap<RegionId, RegionLane>,
+    queued: BTreeMap<TaskId, RegionId>,
+    min_vruntime: u64,
+    default_weight: u32,
+}
+
+impl Default for RegionFairQueue {
+    fn default() -> Self {
+        Self::with_default_weight(DEFAULT_REGION_WEIGHT)
+    }
+}
+
+impl RegionFairQueue {
+    /// Creates an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty queue whose regions start at `weight` until given
+    /// their own; zero is treated as one.
+    #[must_use]
+    pub fn with_default_weight(weight: u32) -> Self {
+        Self {
+            lanes: BTreeMap::new(),
+            queued: BTreeMap::new(),
+            min_vruntime: 0,
+            default_weight: weight.max(1),
+        }
+    }
+
+    /// Returns the number of queued tasks.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Returns true if no task is queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// Returns true if `task` is queued.
+    #[must_use]
+    pub fn contains(&self, task: TaskId) -> bool {
+        self.queued.contains_key(&task)
+    }
+
+    /// Queues `task` for `region`. Returns false if it was already queued.
+    pub fn push(&mut self, region: RegionId, task: TaskId, priority: u8) -> bool {
+        if self.queued.contains_key(&task) {
+            return false;
+        }
+        let (min_vruntime, default_weight) = (self.min_vruntime, self.default_weight);
+        let lane = self
+            .lanes
+            .entry(region)
+            .or_insert_with(|| RegionLane::new(default_weight, min_vruntime));
+        if lane.ready.is_empty() {
+            lane.vruntime = lane.vruntime.max(min_vruntime);
+        }
+        lane.ready.schedule(task, priority);
+        self.queued.insert(task, region);
+        true
+    }
+
+    /// Dispatches the next task, charging its region.
+    ///
+    /// `rng_hint` breaks ties among equal-priority tasks within the region.
+    #[must_use]
+    pub fn pop(&mut self, rng_hint: u64) -> Option<(TaskId, RegionId)> {
+        let region = self
+            .lanes
+            .iter()
+            .filter(|(_, lane)| !lane.ready.is_empty())
+            .min_by_key(|(_, lane)| lane.vruntime)
+            .map(|(region, _)| *region)?;
+        let lane = self.lanes.get_mut(&region)?;
+        let task = lane.ready.pop_ready_only_with_hint(rng_hint)?;
+        lane.charge();
+        let charged = lane.vruntime;
+        self.queued.remove(&task);
+
+        // The dispatched region counts as active: its task is usually
+        // requeued right away and must not be clamped forward.
+        let floor = self
+            .lanes
+            .values()
+            .filter(|lane| !lane.ready.is_empty())
+            .map(|lane| lane.vruntime)
+            .min()
+            .map_or(charged, |active| active.min(charged));
+        self.min_vruntime = self.min_vruntime.max(floor);
+        Some((task, region))
+    }
+
+    /// Removes `task` if it is queued. Returns true if it was.
+    pub fn remove(&mut self, task: TaskId) -> bool {
+        let Some(region) = self.queued.remove(&task) else {
+            return false;
+        };
+        if let Some(lane) = self.lanes.get_mut(&region) {
+            lane.ready.remove(task);
+        }
+        true
+    }
+
+    /// Raises the priority of queued `task` within its region. Returns true
+    /// if it was queued below `priority`.
+    pub fn promote(&mut self, task: TaskId, priority: u8) -> bool {
+        let Some(region) = self.queued.get(&task) else {
+            return false;
+        };
+        self.lanes
+            .get_mut(region)
+            .is_some_and(|lane| lane.ready.promote_ready(task, priority))
+    }
+
+    /// Sets the weight of `region`; zero is treated as one.
+    ///
+    /// The new weight applies to every dispatch after the call; virtual
+    /// runtime already accumulated is kept.
+    pub fn set_weight(&mut self, region: RegionId, weight: u32) {
+        let weight = weight.max(1);
+        let min_vruntime = self.min_vruntime;
+        self.lanes
+            .entry(region)
+            .and_modify(|lane| lane.weight = weight)
+            .or_insert_with(|| RegionLane::new(weight, min_vruntime));
+    }
+
+    /// Returns the weight of `region`.
+    #[must_use]
+    pub fn weight(&self, region: RegionId) -> u32 {
+        self.lanes
+            .get(&region)
+            .map_or(self.default_weight, |lane| lane.weight)
+    }
+
+    /// Drops the accounting of idle regions still at the default weight.
+    ///
+    /// Such a region carries nothing forward: it re-enters at the minimum
+    /// virtual runtime anyway. Regions with an explicit weight keep it.
+    pub fn forget_idle(&mut self) {
+        let default_weight = self.default_weight;
+        self.lanes
+            .retain(|_, lane| !lane.ready.is_empty() || lane.weight != default_weight);
+    }
+
+    /// Returns the accounting of every region the queue has seen, ordered by
+    /// region id.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<RegionVruntime> {
+        self.lanes
+            .iter()
+            .map(|(region, lane)| RegionVruntime {
+                region: *region,
+                weight: lane.weight,
+                vruntime: lane.vruntime,
+                dispatched: lane.dispatched,
+                ready: lane.ready.len(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    fn region(n: u32) -> RegionId {
+        RegionId::new_for_test(n, 0)
+    }
+
+    fn task(n: u32) -> TaskId {
+        TaskId::new_for_test(n, 0)
+    }
+
+    /// Keeps every task permanently ready and counts dispatches per region.
+    fn run(queue: &mut RegionFairQueue, dispatches: usize) -> BTreeMap<RegionId, usize> {
+        let mut counts = BTreeMap::new();
+        for step in 0..dispatches {
+            let (t, r) = queue.pop(step as u64).expect("work available");
+            *counts.entry(r).or_insert(0) += 1;
+            assert!(queue.push(r, t, 0));
+        }
+        counts
+    }
+
+    #[test]
+    fn crowded_region_does_not_monopolize() {
+        let mut queue = RegionFairQueue::new();
+        for n in 0..1000 {
+            queue.push(region(1), task(n), 0);
+        }
+        queue.push(region(2), task(5000), 0);
+
+        let counts = run(&mut queue, 400);
+        assert_eq!(counts[&region(1)], 200);
+        assert_eq!(counts[&region(2)], 200);
+    }
+
+    #[test]
+    fn shares_follow_weights() {
+        let mut queue = RegionFairQueue::new();
+        queue.set_weight(region(1), 1024);
+        queue.set_weight(region(2), 4096);
+        for n in 0..1000 {
+            queue.push(region(1), task(n), 0);
+        }
+        queue.push(region(2), task(5000), 0);
+
+        let counts = run(&mut queue, 5000);
+        assert_eq!(counts[&region(1)], 1000);
+        assert_eq!(counts[&region(2)], 4000);
+    }
+
+    #[test]
+    fn weight_change_applies_to_later_dispatches() {
+        let mut queue = RegionFairQueue::new();
+        queue.push(region(1), task(1), 0);
+        queue.push(region(2), task(2), 0);
+        let before = run(&mut queue, 100);
+        assert_eq!(before[&region(1)], 50);
+
+        queue.set_weight(region(1), 4 * DEFAULT_REGION_WEIGHT);
+        assert_eq!(queue.weight(region(1)), 4 * DEFAULT_REGION_WEIGHT);
+        let after = run(&mut queue, 100);
+        assert_eq!(after[&region(1)], 80);
+        assert_eq!(after[&region(2)], 20);
+    }
+
+    #[test]
+    fn idle_region_cannot_bank_credit() {
+        let mut queue = RegionFairQueue::new();
+        queue.push(region(1), task(1), 0);
+        run(&mut queue, 100);
+
+        // Region 2 arrives late; it must share, not run 100 times in a row.
+        queue.push(region(2), task(2), 0);
+        let counts = run(&mut queue, 10);
+        assert!(counts[&region(1)] >= 4, "{counts:?}");
+        assert!(counts[&region(2)] >= 4, "{counts:?}");
+    }
+
+    #[test]
+    fn remove_and_snapshot() {
+        let mut queue = RegionFairQueue::new();
+        assert!(queue.push(region(1), task(1), 0));
+        assert!(!queue.push(region(1), task(1), 0));
+        queue.push(region(2), task(2), 0);
+        assert!(queue.remove(task(1)));
+        assert!(!queue.remove(task(1)));
+        assert_eq!(queue.len(), 1);
+
+        assert_eq!(queue.pop(0), Some((task(2), region(2))));
+        assert!(queue.pop(0).is_none());
+        let snapshot = queue.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].dispatched, 0);
+        assert_eq!(snapshot[1].dispatched, 1);
+        assert_eq!(snapshot[1].vruntime, VRUNTIME_QUANTUM / 1024);
+        assert_eq!(snapshot[1].ready, 0);
+    }
+
+    #[test]
+    fn forget_idle_keeps_ready_and_weighted_regions() {
+        let mut queue = RegionFairQueue::with_default_weight(512);
+        assert_eq!(queue.weight(region(9)), 512);
+        queue.set_weight(region(1), 2048);
+        queue.push(region(2), task(2), 0);
+        queue.push(region(3), task(3), 0);
+        assert_eq!(queue.pop(0), Some((task(2), region(2))));
+
+        queue.forget_idle();
+        let regions: Vec<_> = queue.snapshot().iter().map(|r| r.region).collect();
+        assert_eq!(regions, vec![region(1), region(3)]);
+    }
+}
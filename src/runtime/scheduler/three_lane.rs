@@ -135,6 +135,7 @@ use crate::runtime::io_driver::IoDriverHandle;
 use crate::runtime::scheduler::global_injector::{GlobalInjector, PriorityTask};
 use crate::runtime::scheduler::local_queue::{self, LocalQueue};
 use crate::runtime::scheduler::priority::Scheduler as PriorityScheduler;
+use crate::runtime::scheduler::region_fair::{RegionFairQueue, RegionFairness, RegionVruntime};
 use crate::runtime::scheduler::swarm_evidence::{
     SCHEDULER_EVIDENCE_SCHEMA_VERSION, SchedulerEvidenceArtifact, SchedulerEvidenceMetrics,
     SchedulerKnobProfile, SchedulerTopologyDescriptor, SchedulerWorkloadClass,
@@ -145,7 +146,7 @@ use crate::runtime::{RuntimeState, TaskTable};
 use crate::sync::ContendedMutex;
 use crate::time::TimerDriverHandle;
 use crate::tracing_compat::{error, trace};
-use crate::types::{CxInner, TaskId, Time};
+use crate::types::{CxInner, RegionId, TaskId, Time};
 use crate::util::{CachePadded, DetHashMap, DetHasher, DetRng};
 use parking_lot::Mutex;
 use parking_lot::RwLock;
@@ -324,6 +325,15 @@ fn local_ready_queue(initial: VecDeque<TaskId>) -> LocalReadyQueue {
     Mutex::new(LocalReadyQueueInner::new(initial))
 }
 
+/// What one [`ThreeLaneWorker::run_turn`] call did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct WorkerTurn {
+    /// Tasks polled during the turn.
+    pub(crate) polled: usize,
+    /// The turn stopped on its poll or time budget rather than running dry.
+    pub(crate) budget_exhausted: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum IoPhaseOutcome {
     /// This worker made useful I/O progress (work may now be runnable).
@@ -1498,6 +1508,9 @@ pub struct ThreeLaneScheduler {
     /// Lock-free spawn intake drained by workers at dispatch time
     /// (br-asupersync-dx-core-api-v2-u1z5hn.1.3). `None` in direct mode.
     spawn_mailbox: Option<Arc<crate::runtime::spawn_mailbox::SpawnMailbox>>,
+    /// Region-fair ready arbiter shared by every worker. `None` in
+    /// task-fair mode.
+    region_fair: Option<Arc<Mutex<RegionFairQueue>>>,
 }
 
 /// Discriminator for [`ThreeLaneScheduler::schedule_internal`]
@@ -1820,6 +1833,9 @@ impl ThreeLaneScheduler {
                 adaptive_batch_state: AdaptiveBatchRuntimeState::default(),
                 steal_locality_counters: StealLocalityCounters::default(),
                 scheduler_evidence: scheduler_evidence.clone(),
+                region_fair: None,
+                region_fair_staging: Vec::new(),
+                region_fair_unresolved: VecDeque::new(),
             });
         }
 
@@ -1843,6 +1859,7 @@ impl ThreeLaneScheduler {
             placement_mode: SchedulerPlacementMode::default(),
             worker_cohort_map: None,
             cohort_count: 1,
+            region_fair: None,
         }
     }
 
@@ -2329,6 +2346,58 @@ impl ThreeLaneScheduler {
         &mut self.workers[worker_id]
     }
 
+    /// Selects how ready dispatches are shared between regions.
+    ///
+    /// Under [`RegionFairness::RegionFair`] workers stage their `Send` ready
+    /// work into one shared [`RegionFairQueue`] and dispatch from the region
+    /// with the smallest weighted virtual runtime; regions start at
+    /// `default_weight`. Pinned `!Send` tasks stay on their owner worker and
+    /// remain task-fair. Must be called before the workers are taken.
+    pub fn set_region_fairness(&mut self, fairness: RegionFairness, default_weight: u32) {
+        let arbiter = fairness.is_region_fair().then(|| {
+            Arc::new(Mutex::new(RegionFairQueue::with_default_weight(
+                default_weight,
+            )))
+        });
+        for worker in &mut self.workers {
+            worker.region_fair.clone_from(&arbiter);
+        }
+        self.region_fair = arbiter;
+    }
+
+    /// Returns the active region fairness mode.
+    #[must_use]
+    pub fn region_fairness(&self) -> RegionFairness {
+        if self.region_fair.is_some() {
+            RegionFairness::RegionFair
+        } else {
+            RegionFairness::TaskFair
+        }
+    }
+
+    /// Sets the scheduling weight of `region`; zero is treated as one.
+    ///
+    /// Applies to every dispatch after the call. Returns false, changing
+    /// nothing, when the scheduler is task-fair.
+    pub fn set_region_weight(&self, region: RegionId, weight: u32) -> bool {
+        let Some(arbiter) = &self.region_fair else {
+            return false;
+        };
+        arbiter.lock().set_weight(region, weight);
+        true
+    }
+
+    /// Returns per-region virtual runtime accounting, ordered by region id.
+    ///
+    /// Empty in task-fair mode. Idle regions at the default weight are
+    /// dropped whenever the arbiter drains.
+    #[must_use]
+    pub fn region_vruntimes(&self) -> Vec<RegionVruntime> {
+        self.region_fair
+            .as_ref()
+            .map_or_else(Vec::new, |arbiter| arbiter.lock().snapshot())
+    }
+
     /// Returns a reference to the global injector.
     #[must_use]
     pub fn global_injector(&self) -> Arc<GlobalInjector> {
@@ -2916,6 +2985,16 @@ pub struct ThreeLaneWorker {
     steal_locality_counters: StealLocalityCounters,
     /// Optional shared collector for runtime scheduler evidence snapshots.
     scheduler_evidence: Option<Arc<Mutex<SchedulerEvidenceCollector>>>,
+    /// Shared region-fair ready arbiter (region-fair mode only).
+    ///
+    /// Phase 3 stages this worker's `Send` ready work into it and dispatches
+    /// by weighted region virtual runtime.
+    region_fair: Option<Arc<Mutex<RegionFairQueue>>>,
+    /// Scratch buffer for ready tasks being staged into `region_fair`.
+    region_fair_staging: Vec<TaskId>,
+    /// Staged tasks without a task record, dispatched task-fair ahead of
+    /// the arbiter.
+    region_fair_unresolved: VecDeque<TaskId>,
 }
 
 /// Worker-local counters for preferred-vs-remote steal outcomes.
@@ -4497,6 +4576,7 @@ impl ThreeLaneWorker {
                 if !self.fast_queue.is_empty()
                     || self.global.has_cancel_work()
                     || self.global.has_ready_work()
+                    || self.region_fair_has_ready()
                     || self.pending_cancel_dispatch_ready.load(Ordering::Acquire)
                     || self
                         .spawn_mailbox
@@ -5645,6 +5725,9 @@ impl ThreeLaneWorker {
 
         // ── PHASE 4: Steal from other workers ────────────────────────
         if let Some(task) = self.try_steal() {
+            if let Some(arbiter) = self.region_fair.clone() {
+                return self.try_region_fair_ready_work(&arbiter, Some(task));
+            }
             self.record_ready_dispatch();
             return Some(self.dispatch_with_adaptive_epoch(task));
         }
@@ -5740,6 +5823,11 @@ impl ThreeLaneWorker {
             return Some(self.dispatch_with_adaptive_epoch(task));
         }
 
+        // Region-fair mode: all Send ready work goes through the arbiter.
+        if let Some(arbiter) = self.region_fair.clone() {
+            return self.try_region_fair_ready_work(&arbiter, None);
+        }
+
         // ── FAIRNESS LOGIC: Balance stolen work vs local work ───────
         // If we've dispatched too many consecutive stolen tasks, give local
         // work a chance to prevent starvation.
@@ -5806,6 +5894,93 @@ impl ThreeLaneWorker {
         None
     }
 
+    /// Dispatches the next `Send` ready task through the region-fair arbiter.
+    ///
+    /// Every ready task this worker can see (its fast queue, prefetched and
+    /// shared global ready work, its priority scheduler, and `stolen`) is
+    /// staged into the arbiter first, so the choice is made over all of
+    /// them rather than in arrival order.
+    fn try_region_fair_ready_work(
+        &mut self,
+        arbiter: &Arc<Mutex<RegionFairQueue>>,
+        stolen: Option<TaskId>,
+    ) -> Option<TaskId> {
+        let mut staged = std::mem::take(&mut self.region_fair_staging);
+        staged.extend(stolen);
+        while let Some(task) = self.fast_queue.pop() {
+            staged.push(task);
+        }
+        // The prefetch buffer is kept newest-first.
+        staged.extend(self.global_ready_buffer.drain(..).rev().map(|pt| pt.task));
+        for _ in 0..self.global.ready_count() {
+            let Some(pt) = self.global.pop_ready() else {
+                break;
+            };
+            staged.push(pt.task);
+        }
+        {
+            let rng_hint = self.rng.next_u64();
+            let mut local = self.local.lock();
+            while let Some(task) = local.pop_ready_only_with_hint(rng_hint) {
+                staged.push(task);
+            }
+        }
+
+        let placements: SmallVec<[Option<(RegionId, u8, bool)>; 16]> =
+            self.with_task_table_ref(|tt| {
+                staged
+                    .iter()
+                    .map(|&task| {
+                        tt.task(task).map(|record| {
+                            (record.owner, record.sched_priority, record.is_local())
+                        })
+                    })
+                    .collect()
+            });
+
+        let rng_hint = self.rng.next_u64();
+        let (next, more_ready) = {
+            let mut queue = arbiter.lock();
+            for (&task, placement) in staged.iter().zip(placements) {
+                match placement {
+                    Some((region, priority, false)) => {
+                        queue.push(region, task, priority);
+                    }
+                    // Pinned tasks never leave their owner worker.
+                    Some((_, _, true)) => self.local_ready.lock().push_back(task),
+                    None => self.region_fair_unresolved.push_back(task),
+                }
+            }
+            let next = self
+                .region_fair_unresolved
+                .pop_front()
+                .or_else(|| queue.pop(rng_hint).map(|(task, _)| task));
+            if queue.is_empty() {
+                queue.forget_idle();
+            }
+            (next, !queue.is_empty())
+        };
+        staged.clear();
+        self.region_fair_staging = staged;
+
+        // Let an idle peer share the backlog; a peer blocked in the reactor
+        // is left alone, since this worker keeps draining anyway.
+        if more_ready {
+            self.coordinator.wake_one_parker();
+        }
+        let task = next?;
+        self.record_ready_dispatch();
+        self.fast_queue_dispatch_streak = 0;
+        Some(self.dispatch_with_adaptive_epoch(task))
+    }
+
+    #[inline]
+    fn region_fair_has_ready(&self) -> bool {
+        self.region_fair
+            .as_ref()
+            .is_some_and(|arbiter| !arbiter.lock().is_empty())
+    }
+
     /// Record a cancel dispatch and update max streak metric.
     #[inline]
     fn record_cancel_dispatch(&mut self, base_limit: usize, effective_limit: usize) {
@@ -5934,8 +6109,13 @@ impl ThreeLaneWorker {
         let fast_ready = self.fast_queue.len();
         let pinned_local_ready = self.local_ready.lock().len();
         let local_priority_ready = self.local.lock().approx_ready_len();
+        let region_fair_ready = self
+            .region_fair
+            .as_ref()
+            .map_or(0, |arbiter| arbiter.lock().len());
 
         global_ready
+            .saturating_add(region_fair_ready)
             .saturating_add(prefetched_global_ready)
             .saturating_add(fast_ready)
             .saturating_add(pinned_local_ready)
@@ -6250,6 +6430,98 @@ impl ThreeLaneWorker {
         false
     }
 
+    /// Runs one bounded scheduling turn on the calling thread without parking.
+    ///
+    /// This is the embedded-host counterpart of [`run_loop`](Self::run_loop):
+    /// it consumes any pending unpark permit, polls the I/O driver once with a
+    /// zero timeout, then dispatches tasks in the usual lane order until no
+    /// work is runnable, `max_polls` tasks have run, or the scheduler clock
+    /// reaches `deadline`.
+    pub(crate) fn run_turn(&mut self, max_polls: usize, deadline: Option<Time>) -> WorkerTurn {
+        let _guard = ScopedLocalScheduler::new(Arc::clone(&self.local));
+        let _queue_guard = LocalQueue::set_current(self.fast_queue.clone());
+        let _local_ready_guard = ScopedLocalReady::new(Arc::clone(&self.local_ready));
+        let _worker_guard = ScopedWorkerId::new(self.id);
+
+        // This turn answers any wake published since the last one.
+        self.parker.park_timeout(Duration::ZERO);
+        if let Some(io) = &self.io_driver
+            && !self.shutdown.load(Ordering::Acquire)
+        {
+            let _ = io.try_turn_with(Some(Duration::ZERO), |_, _| {});
+        }
+
+        let mut turn = WorkerTurn::default();
+        while !self.shutdown.load(Ordering::Relaxed) {
+            let out_of_time =
+                deadline.is_some_and(|deadline| self.current_scheduler_time() >= deadline);
+            if turn.polled >= max_polls || out_of_time {
+                turn.budget_exhausted = true;
+                break;
+            }
+            if let Some(task) = self.next_task() {
+                self.execute(task);
+                turn.polled += 1;
+                continue;
+            }
+            if !self.schedule_ready_finalizers() {
+                break;
+            }
+        }
+
+        // A turn boundary plays the role of a park for streak fairness.
+        self.cancel_streak = 0;
+        self.ready_dispatch_streak = 0;
+        turn
+    }
+
+    /// Returns true when a turn started now would find work to dispatch.
+    pub(crate) fn has_pending_work(&self) -> bool {
+        let now = self.current_scheduler_time();
+        let timer_due = self
+            .timer_driver
+            .as_ref()
+            .and_then(TimerDriverHandle::next_deadline)
+            .is_some_and(|deadline| deadline <= now);
+        timer_due
+            || !self.fast_queue.is_empty()
+            || !self.global_ready_buffer.is_empty()
+            || self.region_fair_has_ready()
+            || self.global.has_runnable_work(now)
+            || self.pending_cancel_dispatch_ready.load(Ordering::Acquire)
+            || self
+                .spawn_mailbox
+                .as_ref()
+                .is_some_and(|mailbox| !mailbox.is_empty())
+            || !crate::runtime::spawn_mailbox::local_spawn_lane_is_empty()
+            || !self.local_ready.lock().is_empty()
+            || self.local.lock().has_runnable_work(now)
+    }
+
+    /// Returns the earliest timer or timed-lane deadline this worker waits on.
+    pub(crate) fn next_wake_deadline(&self) -> Option<Time> {
+        let timer_deadline = self
+            .timer_driver
+            .as_ref()
+            .and_then(TimerDriverHandle::next_deadline);
+        let local_deadline = self.local.lock().next_deadline();
+        let global_deadline = self.global.peek_earliest_deadline();
+        [timer_deadline, local_deadline, global_deadline]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Returns the clock this worker dispatches timed work against.
+    pub(crate) fn scheduler_now(&self) -> Time {
+        self.current_scheduler_time()
+    }
+
+    /// Installs a callback run whenever this worker's parker is unparked.
+    pub(crate) fn set_wake_hook(&self, hook: Arc<dyn Fn() + Send + Sync>) -> bool {
+        self.parker.set_wake_hook(hook)
+    }
+
     /// Tries to get cancel work from global or local queues.
     pub(crate) fn try_cancel_work(&mut self) -> Option<TaskId> {
         // Global cancel has priority (cross-thread cancellations)
@@ -6951,7 +7223,13 @@ impl ThreeLaneWorker {
             w
         } else {
             let inner = cx_inner.as_ref().expect("cx_inner missing");
-            let fast_cancel = Arc::clone(&inner.read().fast_cancel);
+            let (fast_cancel, inheritance) = {
+                let guard = inner.read();
+                (
+                    Arc::clone(&guard.fast_cancel),
+                    Arc::clone(&guard.priority_inheritance),
+                )
+            };
             let weak_inner = Arc::downgrade(inner);
             if is_local {
                 Waker::from(Arc::new(ThreeLaneLocalWaker {
@@ -6972,6 +7250,7 @@ impl ThreeLaneWorker {
                     global: Arc::clone(&self.global),
                     coordinator: Arc::clone(&self.coordinator),
                     priority,
+                    inheritance: Some(inheritance),
                     fast_cancel,
                     cx_inner: weak_inner,
                     scheduler_evidence: self.scheduler_evidence.clone(),
@@ -7051,10 +7330,11 @@ impl ThreeLaneWorker {
         // The worker dispatch quantum is one `Future::poll`. Do not loop on a
         // self-woken task here: returning to `next_task()` is what lets cancel,
         // timed, and ready lanes re-evaluate their fairness gates.
-        let poll_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        crate::runtime::chaos::before_poll();
+        let poll_result = crate::runtime::panic_strategy::catch_unwind(|| {
             let mut cx = Context::from_waker(&waker);
             stored.poll(&mut cx)
-        }));
+        });
 
         let mut credit_adaptive_epoch = true;
         match poll_result {
@@ -7558,6 +7838,8 @@ struct ThreeLaneWaker {
     /// Cached priority to avoid `Weak::upgrade` + `RwLock::read` on every wake.
     /// Safe because `budget.priority` is immutable after task creation.
     priority: u8,
+    /// Priority inherited through held locks, read atomically on each wake.
+    inheritance: Option<Arc<crate::sync::TaskPriorityNode>>,
     fast_cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
     cx_inner: Weak<RwLock<CxInner>>,
     scheduler_evidence: Option<Arc<Mutex<SchedulerEvidenceCollector>>>,
@@ -7569,7 +7851,10 @@ impl ThreeLaneWaker {
         if self.wake_state.notify() {
             // Check for cancellation to route to correct lane (cancel > ready).
             // This ensures "Losers are drained" with high priority even during I/O wakeups.
-            let mut priority = self.priority;
+            let mut priority = self
+                .inheritance
+                .as_ref()
+                .map_or(self.priority, |node| self.priority.max(node.inherited()));
             // Pair with the Release store in `CxInner::fast_cancel` so a wake
             // that observes cancellation also observes the published reason.
             let is_cancelling = self.fast_cancel.load(Ordering::Acquire);
@@ -8670,6 +8955,79 @@ mod tests {
         assert_eq!(task2.unwrap(), TaskId::new_for_test(1, 1));
     }
 
+    #[test]
+    fn region_fair_mode_shares_ready_dispatches_by_region_weight() {
+        use crate::runtime::scheduler::DEFAULT_REGION_WEIGHT;
+
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        let crowded = state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("child region");
+        let sparse = state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("child region");
+        let mut owner = BTreeMap::new();
+        for _ in 0..1000 {
+            let (task, _) = state
+                .create_task(crowded, Budget::INFINITE, async {})
+                .expect("create task");
+            owner.insert(task, crowded);
+        }
+        let (lone, _) = state
+            .create_task(sparse, Budget::INFINITE, async {})
+            .expect("create task");
+        owner.insert(lone, sparse);
+        let state = Arc::new(ContendedMutex::new("runtime_state", state));
+
+        let mut scheduler = ThreeLaneScheduler::new(1, &state);
+        assert!(!scheduler.set_region_weight(sparse, 1), "task-fair by default");
+        scheduler.set_region_fairness(RegionFairness::RegionFair, DEFAULT_REGION_WEIGHT);
+        assert_eq!(scheduler.region_fairness(), RegionFairness::RegionFair);
+        assert!(scheduler.set_region_weight(sparse, 4 * DEFAULT_REGION_WEIGHT));
+        let global = scheduler.global_injector();
+        for &task in owner.keys() {
+            global.inject_ready(task, 0);
+        }
+        let mut workers = scheduler.take_workers();
+        let worker = &mut workers[0];
+
+        // Every dispatched task is ready again at once, like a busy tenant's.
+        let run = |worker: &mut ThreeLaneWorker, dispatches: usize| {
+            let mut counts = BTreeMap::new();
+            for _ in 0..dispatches {
+                let task = worker.next_task().expect("ready work");
+                *counts.entry(owner[&task]).or_insert(0_usize) += 1;
+                global.inject_ready(task, 0);
+            }
+            counts
+        };
+
+        // 1000:1 ready tasks, 1:4 weights: shares follow the weights.
+        let shares = run(worker, 1000);
+        assert_eq!(shares[&crowded], 200, "{shares:?}");
+        assert_eq!(shares[&sparse], 800, "{shares:?}");
+
+        // A weight change applies from the next dispatch on.
+        assert!(scheduler.set_region_weight(sparse, DEFAULT_REGION_WEIGHT));
+        let shares = run(worker, 1000);
+        assert_eq!(shares[&crowded], 500, "{shares:?}");
+        assert_eq!(shares[&sparse], 500, "{shares:?}");
+
+        let vruntimes = scheduler.region_vruntimes();
+        let of = |region| {
+            vruntimes
+                .iter()
+                .find(|entry| entry.region == region)
+                .copied()
+                .expect("region accounted")
+        };
+        assert_eq!(of(crowded).dispatched, 700);
+        assert_eq!(of(sparse).dispatched, 1300);
+        assert_eq!(of(sparse).weight, DEFAULT_REGION_WEIGHT);
+        assert_eq!(of(crowded).vruntime, of(sparse).vruntime);
+    }
+
     #[test]
     fn test_cancel_lane_fairness_limit() {
         let state = Arc::new(ContendedMutex::new("runtime_state", RuntimeState::new()));
@@ -9160,6 +9518,7 @@ mod tests {
             global: Arc::clone(&global),
             coordinator,
             priority: Budget::INFINITE.priority,
+            inheritance: None,
             fast_cancel: Arc::clone(&cx_inner.read().fast_cancel),
             cx_inner: Arc::downgrade(&cx_inner),
             scheduler_evidence: None,
@@ -10287,6 +10646,7 @@ mod tests {
                     global: Arc::clone(&global),
                     coordinator: Arc::clone(&coordinator),
                     priority: 0,
+                    inheritance: None,
                     fast_cancel: Arc::new(std::sync::atomic::AtomicBool::new(false)),
                     cx_inner: Weak::new(),
                     scheduler_evidence: None,
@@ -18,7 +18,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::task::{Context, Poll, Wake, Waker};
 use std::time::Duration;
 
@@ -528,6 +528,7 @@ impl Worker {
         let poll_attempt = stored.poll_count().saturating_add(1);
         let poll_attempt = u32::try_from(poll_attempt).unwrap_or(u32::MAX);
 
+        crate::runtime::chaos::before_poll();
         // Isolate the potentially panicking task poll operation
         let poll_result =
             self.panic_isolator
@@ -878,12 +879,22 @@ impl Wake for WorkStealingWaker {
     }
 }
 
+/// Callback run on every successful [`Parker::unpark`].
+struct WakeHook(Arc<dyn Fn() + Send + Sync>);
+
+impl std::fmt::Debug for WakeHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WakeHook(..)")
+    }
+}
+
 #[derive(Debug)]
 struct ParkerInner {
     notified: AtomicBool,
     waiting: AtomicUsize,
     mutex: Mutex<()>,
     cvar: Condvar,
+    hook: OnceLock<WakeHook>,
 }
 
 /// A mechanism for parking and unparking a worker.
@@ -911,6 +922,7 @@ impl Parker {
                 waiting: AtomicUsize::new(0),
                 mutex: Mutex::new(()),
                 cvar: Condvar::new(),
+                hook: OnceLock::new(),
             }),
         }
     }
@@ -1020,6 +1032,11 @@ impl Parker {
             return;
         }
         crate::runtime::metrics::record_worker_unpark();
+        // The hook must run even when nobody is parked: an embedded driver
+        // never blocks in park(), it relies on the hook to reach its host.
+        if let Some(hook) = self.inner.hook.get() {
+            (hook.0)();
+        }
         // br-asupersync-re7cz3: Dekker-style store-load barrier — see the
         // matching fence in park()/park_timeout(). Without this, unpark's
         // load on `waiting` could be reordered ahead of the CAS publish on
@@ -1077,6 +1094,15 @@ impl Parker {
         true
     }
 
+    /// Installs a callback run after every [`unpark`](Self::unpark) that
+    /// publishes a new permit.
+    ///
+    /// The hook runs on the waking thread and must not block. Only the first
+    /// installation takes effect; returns `false` if a hook was already set.
+    pub(crate) fn set_wake_hook(&self, hook: Arc<dyn Fn() + Send + Sync>) -> bool {
+        self.inner.hook.set(WakeHook(hook)).is_ok()
+    }
+
     #[cfg(test)]
     #[must_use]
     pub(crate) fn notification_pending_for_test(&self) -> bool {
@@ -0,0 +1,639 @@
+//! Structured shutdown reports.
+//!
+//! A [`ShutdownReport`] summarizes what happened while a runtime was asked to
+//! stop: how long each region spent in its cancel fan-out, drain, and finalize
+//! phases, how every task that was live at shutdown ended, how many
+//! obligations had to be force-aborted, and which tasks were slowest to drain.
+//!
+//! The report is produced by [`Runtime::shutdown`](super::Runtime::shutdown)
+//! and [`LabRuntime::shutdown`](crate::lab::LabRuntime::shutdown). Data
+//! collection piggybacks on the region and task transitions that
+//! [`RuntimeState`](super::RuntimeState) already performs; nothing is sampled
+//! unless a shutdown is in progress.
+//!
+//! # Phases
+//!
+//! | Phase | Measured from | Measured to |
+//! |-------|---------------|-------------|
+//! | cancel | region cancel request | cancel fan-out dispatched |
+//! | drain | cancel fan-out dispatched | region enters `Finalizing` |
+//! | finalize | region enters `Finalizing` | region `Closed` |
+//!
+//! If the shutdown budget runs out first, unfinished phases are measured up to
+//! the moment the report was taken and the report is marked incomplete.
+
+use crate::types::{RegionId, TaskId, Time};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Schema identifier embedded in every serialized [`ShutdownReport`].
+pub const SHUTDOWN_REPORT_SCHEMA_VERSION: &str = "asupersync-shutdown-report-v1";
+
+/// Number of tasks listed in [`ShutdownReport::slowest_tasks`].
+pub const SHUTDOWN_REPORT_SLOWEST_TASKS: usize = 8;
+
+/// Aggregate summary of a runtime shutdown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// Schema identifier ([`SHUTDOWN_REPORT_SCHEMA_VERSION`]).
+    pub schema_version: String,
+    /// Whether every region reached `Closed` within the budget.
+    pub complete: bool,
+    /// Why the report is partial, when `complete` is false.
+    pub incomplete_reason: Option<String>,
+    /// Runtime time at which shutdown started.
+    pub started_at_nanos: u64,
+    /// Runtime time at which the report was taken.
+    pub finished_at_nanos: u64,
+    /// Shutdown budget granted versus consumed.
+    pub budget: ShutdownBudgetUsage,
+    /// Task outcome counts across all regions.
+    pub outcomes: ShutdownOutcomeCounts,
+    /// Obligations force-aborted across all regions.
+    pub obligations_force_aborted: u64,
+    /// Per-region breakdown in region-tree preorder.
+    pub regions: Vec<RegionShutdownSummary>,
+    /// Tasks that took longest to drain, slowest first.
+    pub slowest_tasks: Vec<TaskDrainSummary>,
+}
+
+/// Shutdown budget granted versus consumed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownBudgetUsage {
+    /// Budget granted to the shutdown.
+    pub granted_nanos: u64,
+    /// Runtime time consumed by the shutdown.
+    pub consumed_nanos: u64,
+    /// Whether the budget ran out before the runtime became quiescent.
+    pub exhausted: bool,
+}
+
+/// Task outcome counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShutdownOutcomeCounts {
+    /// Tasks that returned `Ok`.
+    pub success: u64,
+    /// Tasks that completed as cancelled.
+    pub cancelled: u64,
+    /// Tasks that returned an error.
+    pub failed: u64,
+    /// Tasks that panicked.
+    pub panicked: u64,
+}
+
+impl ShutdownOutcomeCounts {
+    fn record(&mut self, outcome: ShutdownTaskOutcome) {
+        match outcome {
+            ShutdownTaskOutcome::Success => self.success += 1,
+            ShutdownTaskOutcome::Cancelled => self.cancelled += 1,
+            ShutdownTaskOutcome::Failed => self.failed += 1,
+            ShutdownTaskOutcome::Panicked => self.panicked += 1,
+            ShutdownTaskOutcome::Pending => {}
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.success += other.success;
+        self.cancelled += other.cancelled;
+        self.failed += other.failed;
+        self.panicked += other.panicked;
+    }
+}
+
+/// How a task ended during shutdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownTaskOutcome {
+    /// The task returned `Ok`.
+    Success,
+    /// The task completed as cancelled.
+    Cancelled,
+    /// The task returned an error.
+    Failed,
+    /// The task panicked.
+    Panicked,
+    /// The task was still live when the report was taken.
+    Pending,
+}
+
+impl ShutdownTaskOutcome {
+    /// Returns the stable snake-case name of this outcome.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Cancelled => "cancelled",
+            Self::Failed => "failed",
+            Self::Panicked => "panicked",
+            Self::Pending => "pending",
+        }
+    }
+}
+
+/// Shutdown timing and outcomes for a single region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionShutdownSummary {
+    /// Region identifier.
+    pub region_id: String,
+    /// Parent region identifier, if the parent was part of the shutdown.
+    pub parent_id: Option<String>,
+    /// Depth below the shutdown root.
+    pub depth: usize,
+    /// Whether the region reached `Closed`.
+    pub closed: bool,
+    /// Time spent fanning cancellation out.
+    pub cancel_nanos: u64,
+    /// Time spent waiting for tasks and child regions.
+    pub drain_nanos: u64,
+    /// Time spent running finalizers.
+    pub finalize_nanos: u64,
+    /// Outcomes of tasks owned directly by this region.
+    pub outcomes: ShutdownOutcomeCounts,
+    /// Obligations force-aborted for tasks owned by this region.
+    pub obligations_force_aborted: u64,
+}
+
+/// Drain timing for a single task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDrainSummary {
+    /// Task identifier.
+    pub task_id: String,
+    /// Owning region identifier.
+    pub region_id: String,
+    /// Task label set via `Cx::set_task_type`, if any.
+    pub label: Option<String>,
+    /// Time from cancel request to completion (or to the report, if pending).
+    pub drain_nanos: u64,
+    /// How the task ended.
+    pub outcome: ShutdownTaskOutcome,
+}
+
+impl ShutdownReport {
+    /// Serializes the report as a JSON string.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Emits the report as the final structured shutdown event.
+    pub(crate) fn emit(&self) {
+        // When tracing is compiled out, ensure `self` is still considered used.
+        let _ = self;
+        crate::tracing_compat::info!(
+            schema = SHUTDOWN_REPORT_SCHEMA_VERSION,
+            complete = self.complete,
+            report = %self.to_json(),
+            "runtime shutdown report"
+        );
+    }
+}
+
+impl fmt::Display for ShutdownReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.incomplete_reason {
+            None => writeln!(f, "Shutdown report (complete)")?,
+            Some(reason) => writeln!(f, "Shutdown report (INCOMPLETE: {reason})")?,
+        }
+        writeln!(
+            f,
+            "  budget: {:?} of {:?}{}",
+            Duration::from_nanos(self.budget.consumed_nanos),
+            Duration::from_nanos(self.budget.granted_nanos),
+            if self.budget.exhausted {
+                " (exhausted)"
+            } else {
+                ""
+            }
+        )?;
+        writeln!(
+            f,
+            "  outcomes: success={} cancelled={} failed={} panicked={}",
+            self.outcomes.success,
+            self.outcomes.cancelled,
+            self.outcomes.failed,
+            self.outcomes.panicked
+        )?;
+        writeln!(
+            f,
+            "  obligations force-aborted: {}",
+            self.obligations_force_aborted
+        )?;
+        writeln!(f, "  regions:")?;
+        for region in &self.regions {
+            writeln!(
+                f,
+                "    {:indent$}{} {} cancel={:?} drain={:?} finalize={:?} success={} cancelled={} failed={} panicked={} aborted={}",
+                "",
+                region.region_id,
+                if region.closed { "closed" } else { "OPEN" },
+                Duration::from_nanos(region.cancel_nanos),
+                Duration::from_nanos(region.drain_nanos),
+                Duration::from_nanos(region.finalize_nanos),
+                region.outcomes.success,
+                region.outcomes.cancelled,
+                region.outcomes.failed,
+                region.outcomes.panicked,
+                region.obligations_force_aborted,
+                indent = region.depth * 2,
+            )?;
+        }
+        if !self.slowest_tasks.is_empty() {
+            writeln!(f, "  slowest tasks:")?;
+            for task in &self.slowest_tasks {
+                writeln!(
+                    f,
+                    "    {} {} {} {:?} {}",
+                    task.task_id,
+                    task.label.as_deref().unwrap_or("-"),
+                    task.region_id,
+                    Duration::from_nanos(task.drain_nanos),
+                    task.outcome.as_str()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl crate::console::Render for ShutdownReport {
+    fn render(
+        &self,
+        out: &mut String,
+        _caps: &crate::console::Capabilities,
+        _mode: crate::console::ColorMode,
+    ) {
+        out.push_str(&self.to_string());
+    }
+}
+
+#[cfg(feature = "cli")]
+impl crate::cli::Outputtable for ShutdownReport {
+    fn human_format(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+struct RegionMarks {
+    parent: Option<RegionId>,
+    requested_at: Time,
+    finalizing_at: Option<Time>,
+    closed_at: Option<Time>,
+    outcomes: ShutdownOutcomeCounts,
+    obligations_force_aborted: u64,
+}
+
+#[derive(Debug)]
+struct TaskMarks {
+    region: RegionId,
+    cancel_requested_at: Time,
+    completed_at: Option<Time>,
+    outcome: ShutdownTaskOutcome,
+    label: Option<String>,
+}
+
+/// Collects shutdown transitions while a shutdown is in progress.
+///
+/// Installed on [`RuntimeState`](super::RuntimeState) by the shutdown entry
+/// points and fed from the existing cancel, completion, and region-close
+/// paths.
+#[derive(Debug)]
+pub(crate) struct ShutdownRecorder {
+    started_at: Time,
+    budget: Duration,
+    fanout_done_at: Option<Time>,
+    regions: BTreeMap<RegionId, RegionMarks>,
+    tasks: BTreeMap<TaskId, TaskMarks>,
+}
+
+impl ShutdownRecorder {
+    pub(crate) fn new(started_at: Time, budget: Duration) -> Self {
+        Self {
+            started_at,
+            budget,
+            fanout_done_at: None,
+            regions: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn region_cancel_requested(
+        &mut self,
+        region: RegionId,
+        parent: Option<RegionId>,
+        now: Time,
+    ) {
+        self.regions.entry(region).or_insert_with(|| RegionMarks {
+            parent,
+            requested_at: now,
+            ..RegionMarks::default()
+        });
+    }
+
+    pub(crate) fn task_cancel_requested(&mut self, task: TaskId, region: RegionId, now: Time) {
+        self.tasks.entry(task).or_insert_with(|| TaskMarks {
+            region,
+            cancel_requested_at: now,
+            completed_at: None,
+            outcome: ShutdownTaskOutcome::Pending,
+            label: None,
+        });
+    }
+
+    /// Records the end of the cancel fan-out.
+    pub(crate) fn fanout_dispatched(&mut self, now: Time) {
+        self.fanout_done_at.get_or_insert(now);
+    }
+
+    pub(crate) fn region_finalizing(&mut self, region: RegionId, now: Time) {
+        if let Some(marks) = self.regions.get_mut(&region) {
+            marks.finalizing_at.get_or_insert(now);
+        }
+    }
+
+    pub(crate) fn region_closed(&mut self, region: RegionId, now: Time) {
+        if let Some(marks) = self.regions.get_mut(&region) {
+            let at = *marks.finalizing_at.get_or_insert(now);
+            marks.closed_at.get_or_insert(at.max(now));
+        }
+    }
+
+    pub(crate) fn task_completed(
+        &mut self,
+        task: TaskId,
+        region: RegionId,
+        outcome: ShutdownTaskOutcome,
+        label: Option<String>,
+        now: Time,
+    ) {
+        let Some(region_marks) = self.regions.get_mut(&region) else {
+            return;
+        };
+        region_marks.outcomes.record(outcome);
+        let started_at = self.started_at;
+        let marks = self.tasks.entry(task).or_insert_with(|| TaskMarks {
+            region,
+            cancel_requested_at: started_at,
+            completed_at: None,
+            outcome: ShutdownTaskOutcome::Pending,
+            label: None,
+        });
+        marks.completed_at = Some(now);
+        marks.outcome = outcome;
+        marks.label = label;
+    }
+
+    pub(crate) fn obligations_aborted(&mut self, region: RegionId, count: u64) {
+        if let Some(marks) = self.regions.get_mut(&region) {
+            marks.obligations_force_aborted += count;
+        }
+    }
+
+    /// Builds the report as of `finished_at`.
+    ///
+    /// `incomplete_reason` is `None` when the runtime reached quiescence.
+    pub(crate) fn finish(
+        self,
+        finished_at: Time,
+        incomplete_reason: Option<String>,
+    ) -> ShutdownReport {
+        let elapsed = |from: Time, to: Time| to.as_nanos().saturating_sub(from.as_nanos());
+        let fanout_done_at = self.fanout_done_at.unwrap_or(self.started_at);
+
+        let mut children: BTreeMap<Option<RegionId>, Vec<RegionId>> = BTreeMap::new();
+        for (&id, marks) in &self.regions {
+            let parent = marks
+                .parent
+                .filter(|parent| self.regions.contains_key(parent));
+            children.entry(parent).or_default().push(id);
+        }
+
+        let mut regions = Vec::with_capacity(self.regions.len());
+        let mut outcomes = ShutdownOutcomeCounts::default();
+        let mut obligations_force_aborted = 0;
+        let mut stack: Vec<(RegionId, usize)> = children
+            .get(&None)
+            .map(|roots| roots.iter().rev().map(|&id| (id, 0)).collect())
+            .unwrap_or_default();
+        while let Some((id, depth)) = stack.pop() {
+            let marks = &self.regions[&id];
+            let drain_start = fanout_done_at.max(marks.requested_at);
+            let drain_end = marks.finalizing_at.unwrap_or(finished_at);
+            let finalize_nanos = marks
+                .finalizing_at
+                .map_or(0, |at| elapsed(at, marks.closed_at.unwrap_or(finished_at)));
+            outcomes.merge(marks.outcomes);
+            obligations_force_aborted += marks.obligations_force_aborted;
+            regions.push(RegionShutdownSummary {
+                region_id: id.to_string(),
+                parent_id: marks.parent.map(|parent| parent.to_string()),
+                depth,
+                closed: marks.closed_at.is_some(),
+                cancel_nanos: elapsed(marks.requested_at, drain_start),
+                drain_nanos: elapsed(drain_start, drain_end),
+                finalize_nanos,
+                outcomes: marks.outcomes,
+                obligations_force_aborted: marks.obligations_force_aborted,
+            });
+            if let Some(kids) = children.get(&Some(id)) {
+                stack.extend(kids.iter().rev().map(|&kid| (kid, depth + 1)));
+            }
+        }
+
+        let mut slowest_tasks: Vec<TaskDrainSummary> = self
+            .tasks
+            .into_iter()
+            .map(|(id, marks)| TaskDrainSummary {
+                task_id: id.to_string(),
+                region_id: marks.region.to_string(),
+                label: marks.label,
+                drain_nanos: elapsed(
+                    marks.cancel_requested_at,
+                    marks.completed_at.unwrap_or(finished_at),
+                ),
+                outcome: marks.outcome,
+            })
+            .collect();
+        // `self.tasks` iterates in id order, so the stable sort keeps ties
+        // deterministic.
+        slowest_tasks.sort_by(|a, b| b.drain_nanos.cmp(&a.drain_nanos));
+        slowest_tasks.truncate(SHUTDOWN_REPORT_SLOWEST_TASKS);
+
+        let granted_nanos = u64::try_from(self.budget.as_nanos()).unwrap_or(u64::MAX);
+        let consumed_nanos = elapsed(self.started_at, finished_at);
+        ShutdownReport {
+            schema_version: SHUTDOWN_REPORT_SCHEMA_VERSION.to_string(),
+            complete: incomplete_reason.is_none(),
+            budget: ShutdownBudgetUsage {
+                granted_nanos,
+                consumed_nanos,
+                exhausted: incomplete_reason.is_some() && consumed_nanos >= granted_nanos,
+            },
+            incomplete_reason,
+            started_at_nanos: self.started_at.as_nanos(),
+            finished_at_nanos: finished_at.as_nanos(),
+            outcomes,
+            obligations_force_aborted,
+            regions,
+            slowest_tasks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::cx::Cx;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::types::Budget;
+    use std::task::Poll;
+
+    fn wait_for_cancel() -> impl std::future::Future<Output = bool> {
+        std::future::poll_fn(|_| {
+            if Cx::with_current(|cx| cx.checkpoint().is_err()).unwrap_or(false) {
+                Poll::Ready(true)
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    #[test]
+    fn lab_shutdown_reports_slow_drain_and_outcomes() {
+        let mut lab = LabRuntime::new(LabConfig::new(42));
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let child = lab
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("child region");
+
+        let (slow, _slow_handle) = lab
+            .state
+            .create_task(child, Budget::INFINITE, async {
+                let cx = Cx::current().expect("task Cx");
+                cx.set_task_type("slow.drain");
+                crate::time::sleep(cx.now(), Duration::from_millis(250)).await;
+            })
+            .expect("slow task");
+        let (cancelled, _cancelled_handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async {
+                let _ = wait_for_cancel().await;
+            })
+            .expect("cancelled task");
+        let (panicking, _panicking_handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async {
+                if wait_for_cancel().await {
+                    panic!("shutdown panic");
+                }
+            })
+            .expect("panicking task");
+        for task in [slow, cancelled, panicking] {
+            lab.scheduler.lock().schedule(task, 0);
+        }
+        lab.run_until_idle();
+
+        let report = lab.shutdown(Duration::from_secs(1));
+
+        assert!(report.complete, "{report}");
+        assert_eq!(report.incomplete_reason, None);
+        assert!(!report.budget.exhausted);
+        assert_eq!(report.budget.granted_nanos, 1_000_000_000);
+        assert_eq!(report.budget.consumed_nanos, 250_000_000);
+        assert_eq!(
+            report.outcomes,
+            ShutdownOutcomeCounts {
+                success: 1,
+                cancelled: 1,
+                failed: 0,
+                panicked: 1,
+            }
+        );
+
+        let region_ids: Vec<&str> = report
+            .regions
+            .iter()
+            .map(|region| region.region_id.as_str())
+            .collect();
+        assert_eq!(region_ids, [root.to_string(), child.to_string()]);
+        assert_eq!(report.regions[1].depth, 1);
+        assert!(report.regions.iter().all(|region| region.closed));
+        assert_eq!(report.regions[1].drain_nanos, 250_000_000);
+        assert_eq!(report.regions[1].outcomes.success, 1);
+
+        let slowest = &report.slowest_tasks[0];
+        assert_eq!(slowest.task_id, slow.to_string());
+        assert_eq!(slowest.label.as_deref(), Some("slow.drain"));
+        assert_eq!(slowest.drain_nanos, 250_000_000);
+        assert_eq!(slowest.outcome, ShutdownTaskOutcome::Success);
+        assert!(report.to_string().contains("slow.drain"));
+    }
+
+    #[test]
+    fn lab_shutdown_budget_exhaustion_produces_partial_report() {
+        let mut lab = LabRuntime::new(LabConfig::new(7));
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (task, _handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, std::future::pending::<()>())
+            .expect("stuck task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_until_idle();
+
+        let report = lab.shutdown(Duration::from_millis(100));
+
+        assert!(!report.complete);
+        assert!(report.budget.exhausted);
+        assert!(report.to_string().contains("INCOMPLETE"));
+        let json = serde_json::to_value(&report).expect("report serializes");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "schema_version": "asupersync-shutdown-report-v1",
+                "complete": false,
+                "incomplete_reason": "shutdown budget of 100ms exhausted with 1 live tasks",
+                "started_at_nanos": 0,
+                "finished_at_nanos": 100_000_000,
+                "budget": {
+                    "granted_nanos": 100_000_000,
+                    "consumed_nanos": 100_000_000,
+                    "exhausted": true
+                },
+                "outcomes": { "success": 0, "cancelled": 0, "failed": 0, "panicked": 0 },
+                "obligations_force_aborted": 0,
+                "regions": [{
+                    "region_id": root.to_string(),
+                    "parent_id": null,
+                    "depth": 0,
+                    "closed": false,
+                    "cancel_nanos": 0,
+                    "drain_nanos": 100_000_000,
+                    "finalize_nanos": 0,
+                    "outcomes": { "success": 0, "cancelled": 0, "failed": 0, "panicked": 0 },
+                    "obligations_force_aborted": 0
+                }],
+                "slowest_tasks": [{
+                    "task_id": task.to_string(),
+                    "region_id": root.to_string(),
+                    "label": null,
+                    "drain_nanos": 100_000_000,
+                    "outcome": "pending"
+                }]
+            })
+        );
+        let round_trip: ShutdownReport =
+            serde_json::from_str(&report.to_json()).expect("report deserializes");
+        assert_eq!(round_trip, report);
+    }
+}
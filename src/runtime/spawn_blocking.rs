@@ -23,7 +23,10 @@
 //! ```
 
 use crate::cx::Cx;
-use crate::runtime::blocking_pool::{BlockingPoolHandle, BlockingTaskHandle};
+use crate::runtime::blocking_pool::{
+    BlockingKind, BlockingPoolHandle, BlockingSpawnError, BlockingTaskHandle, BlockingWork,
+};
+use crate::runtime::config::BlockingQueueFullPolicy;
 use parking_lot::Mutex;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -243,7 +246,7 @@ where
 {
     let (tx, rx) = BlockingOneshot::new();
     let handle = pool.spawn(move || {
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        let result = crate::runtime::panic_strategy::catch_unwind(f);
         tx.send(result);
     });
 
@@ -253,6 +256,48 @@ where
     result
 }
 
+/// Runs `f` on the lane for `kind`, applying that lane's queue-full policy.
+///
+/// With [`BlockingQueueFullPolicy::Wait`] the caller yields until the lane
+/// has capacity; with [`BlockingQueueFullPolicy::Reject`] a full queue fails
+/// immediately and is counted in the lane's rejection metric.
+pub(crate) async fn spawn_blocking_kind_on_pool<F, T>(
+    pool: BlockingPoolHandle,
+    kind: BlockingKind,
+    f: F,
+) -> Result<T, BlockingSpawnError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = BlockingOneshot::new();
+    let mut work: BlockingWork = Box::new(move || {
+        let result = crate::runtime::panic_strategy::catch_unwind(f);
+        tx.send(result);
+    });
+
+    let handle = loop {
+        match pool.try_submit(kind, work) {
+            Ok(handle) => break handle,
+            Err((BlockingSpawnError::QueueFull { .. }, returned))
+                if pool.queue_full_policy(kind) == BlockingQueueFullPolicy::Wait =>
+            {
+                work = returned;
+                crate::runtime::yield_now::yield_now().await;
+            }
+            Err((error, _work)) => {
+                pool.note_rejected(error);
+                return Err(error);
+            }
+        }
+    };
+
+    let mut guard = CancelOnDrop::new(handle);
+    let result = rx.await;
+    guard.mark_done();
+    Ok(result)
+}
+
 struct FallbackGuard;
 
 impl Drop for FallbackGuard {
@@ -298,7 +343,7 @@ where
                 .lock()
                 .take()
                 .expect("spawn_blocking_on_thread fn missing");
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+            let result = crate::runtime::panic_strategy::catch_unwind(f);
             tx.send(result);
         });
 
@@ -453,6 +498,108 @@ mod tests {
         crate::test_complete!("spawn_blocking_inline_when_no_pool");
     }
 
+    fn bounded_cpu_lane_pool(policy: BlockingQueueFullPolicy) -> crate::runtime::BlockingPool {
+        let cpu = crate::runtime::BlockingPool::with_config(
+            1,
+            1,
+            crate::runtime::blocking_pool::BlockingPoolOptions {
+                queue_capacity: Some(1),
+                queue_full_policy: policy,
+                ..Default::default()
+            },
+        );
+        crate::runtime::BlockingPool::new(1, 1).with_cpu_lane(cpu)
+    }
+
+    /// Occupies the single cpu-lane thread and its one queue slot.
+    fn fill_cpu_lane(pool: &crate::runtime::BlockingPool) -> Arc<(StdMutex<bool>, Condvar)> {
+        let gate = Arc::new((StdMutex::new(false), Condvar::new()));
+        let held = Arc::clone(&gate);
+        pool.spawn_kind(BlockingKind::Cpu, move || {
+            let (lock, condvar) = &*held;
+            let open = lock.lock().unwrap();
+            let _open = condvar.wait_while(open, |open| !*open).unwrap();
+        });
+        while pool.lane_metrics(BlockingKind::Cpu).busy_threads == 0 {
+            std::thread::yield_now();
+        }
+        pool.spawn_kind(BlockingKind::Cpu, || {});
+        gate
+    }
+
+    fn open_gate(gate: &(StdMutex<bool>, Condvar)) {
+        *gate.0.lock().unwrap() = true;
+        gate.1.notify_all();
+    }
+
+    #[test]
+    fn spawn_blocking_kind_rejects_when_lane_full() {
+        init_test("spawn_blocking_kind_rejects_when_lane_full");
+        let pool = bounded_cpu_lane_pool(BlockingQueueFullPolicy::Reject);
+        let gate = fill_cpu_lane(&pool);
+
+        let result = future::block_on(spawn_blocking_kind_on_pool(
+            pool.handle(),
+            BlockingKind::Cpu,
+            || 1,
+        ));
+        let expected = Err(BlockingSpawnError::QueueFull {
+            kind: BlockingKind::Cpu,
+            capacity: 1,
+        });
+        crate::assert_with_log!(result == expected, "typed rejection", expected, result);
+        let rejected = pool.lane_metrics(BlockingKind::Cpu).total_rejected;
+        crate::assert_with_log!(rejected == 1, "rejection counted", 1, rejected);
+
+        let io = future::block_on(spawn_blocking_kind_on_pool(
+            pool.handle(),
+            BlockingKind::Io,
+            || 2,
+        ));
+        crate::assert_with_log!(
+            io == Ok(2),
+            "io lane unaffected",
+            Ok::<_, BlockingSpawnError>(2),
+            io
+        );
+
+        open_gate(&gate);
+        pool.shutdown_and_wait(Duration::from_secs(30));
+        crate::test_complete!("spawn_blocking_kind_rejects_when_lane_full");
+    }
+
+    #[test]
+    fn spawn_blocking_kind_waits_for_lane_capacity() {
+        init_test("spawn_blocking_kind_waits_for_lane_capacity");
+        let pool = bounded_cpu_lane_pool(BlockingQueueFullPolicy::Wait);
+        let gate = fill_cpu_lane(&pool);
+
+        let opener = {
+            let gate = Arc::clone(&gate);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                open_gate(&gate);
+            })
+        };
+        let result = future::block_on(spawn_blocking_kind_on_pool(
+            pool.handle(),
+            BlockingKind::Cpu,
+            || 7,
+        ));
+        opener.join().unwrap();
+
+        crate::assert_with_log!(
+            result == Ok(7),
+            "waited for capacity",
+            Ok::<_, BlockingSpawnError>(7),
+            result
+        );
+        let rejected = pool.lane_metrics(BlockingKind::Cpu).total_rejected;
+        crate::assert_with_log!(rejected == 0, "no rejection under wait policy", 0, rejected);
+        pool.shutdown_and_wait(Duration::from_secs(30));
+        crate::test_complete!("spawn_blocking_kind_waits_for_lane_capacity");
+    }
+
     #[test]
     fn spawn_blocking_runs_in_parallel() {
         init_test("spawn_blocking_runs_in_parallel");
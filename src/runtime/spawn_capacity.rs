@@ -0,0 +1,735 @@
+//! Spawn admission control by live-task count.
+//!
+//! A runtime configured with [`SpawnCapacityConfig::max_live_tasks`] refuses
+//! new tasks once that many are live: the fallible spawn APIs return
+//! [`SpawnError::AtCapacity`] with [`CapacityScope::Runtime`]. Regions
+//! compose with it: [`RegionLimits::max_live_tasks`] bounds the live tasks of
+//! a region and every region nested beneath it, rejecting with
+//! [`CapacityScope::Region`], so a child region never gets more headroom than
+//! its ancestors have left. Each region keeps its subtree count up to date as
+//! tasks are admitted and retired, so the check only walks the ancestor
+//! chain. [`RegionLimits::max_tasks`] still bounds a region's own tasks and
+//! rejects with [`SpawnError::RegionAtCapacity`].
+//!
+//! Spawners that would rather wait than fail await
+//! [`SpawnCapacityGate::wait`]; waiters are released in FIFO order, one per
+//! completed task.
+//! [`RuntimeHandle::spawn_when_available`](crate::runtime::RuntimeHandle::spawn_when_available)
+//! spawns after such a wait and is cancelled by dropping it. The infallible
+//! [`RuntimeHandle::spawn`](crate::runtime::RuntimeHandle::spawn) blocks its
+//! thread instead of dropping the future, except on a runtime worker thread,
+//! where blocking would stall the tasks whose completion frees capacity: there
+//! it fails with [`SpawnError::AtCapacity`].
+//!
+//! [`RegionLimits::max_live_tasks`]: crate::record::RegionLimits::max_live_tasks
+//! [`RegionLimits::max_tasks`]: crate::record::RegionLimits::max_tasks
+//!
+//! Before the hard limit, the soft watermark raises a
+//! [`SpawnPressureEvent`] and lowers the configured
+//! [`SystemPressure`] headroom, so load shedding can start early.
+//!
+//! Cleanup tasks (finalizers) are exempt from every limit. With no limit and
+//! no watermark configured the gate is inert and admission does no extra
+//! work.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::observability::LogEntry;
+use crate::runtime::state::SpawnError;
+use crate::types::{RegionId, SystemPressure};
+
+/// Number of pressure events retained by a gate.
+const PRESSURE_EVENT_CAPACITY: usize = 64;
+
+/// Spawn admission limits for a runtime.
+#[derive(Clone, Default)]
+pub struct SpawnCapacityConfig {
+    /// Maximum live tasks in the runtime; `None` admits without bound.
+    pub max_live_tasks: Option<usize>,
+    /// Live-task count at which spawn pressure is raised.
+    pub soft_watermark: Option<usize>,
+    /// Pressure signal lowered while the watermark is exceeded.
+    pub pressure: Option<Arc<SystemPressure>>,
+}
+
+impl fmt::Debug for SpawnCapacityConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnCapacityConfig")
+            .field("max_live_tasks", &self.max_live_tasks)
+            .field("soft_watermark", &self.soft_watermark)
+            .field("pressure", &self.pressure.is_some())
+            .finish()
+    }
+}
+
+impl SpawnCapacityConfig {
+    /// Limits the runtime to `limit` live tasks.
+    #[must_use]
+    pub fn with_max_live_tasks(mut self, limit: usize) -> Self {
+        self.max_live_tasks = Some(limit);
+        self
+    }
+
+    /// Raises spawn pressure once `watermark` tasks are live.
+    #[must_use]
+    pub fn with_soft_watermark(mut self, watermark: usize) -> Self {
+        self.soft_watermark = Some(watermark);
+        self
+    }
+
+    /// Feeds spawn pressure into `pressure`.
+    #[must_use]
+    pub fn with_pressure(mut self, pressure: Arc<SystemPressure>) -> Self {
+        self.pressure = Some(pressure);
+        self
+    }
+
+    /// Returns `true` if neither a limit nor a watermark is configured.
+    #[must_use]
+    pub const fn is_unlimited(&self) -> bool {
+        self.max_live_tasks.is_none() && self.soft_watermark.is_none()
+    }
+}
+
+/// Which limit rejected a spawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CapacityScope {
+    /// The runtime-wide [`SpawnCapacityConfig::max_live_tasks`].
+    Runtime,
+    /// The `max_live_tasks` of a region, covering its whole subtree.
+    Region(RegionId),
+}
+
+impl fmt::Display for CapacityScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Runtime => f.write_str("runtime"),
+            Self::Region(region) => write!(f, "region {region:?}"),
+        }
+    }
+}
+
+/// Direction of a [`SpawnPressureEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnPressureKind {
+    /// Live tasks reached the soft watermark.
+    Raised,
+    /// Live tasks fell back below the soft watermark.
+    Cleared,
+}
+
+/// Live tasks crossed the soft watermark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnPressureEvent {
+    /// Crossing direction.
+    pub kind: SpawnPressureKind,
+    /// Live tasks after the crossing.
+    pub live_tasks: usize,
+    /// The configured watermark.
+    pub watermark: usize,
+    /// The configured hard limit, if any.
+    pub limit: Option<usize>,
+}
+
+impl SpawnPressureEvent {
+    /// Message shared by every spawn pressure log entry.
+    pub const MESSAGE: &'static str = "spawn pressure";
+    /// Target shared by every spawn pressure log entry.
+    pub const TARGET: &'static str = "asupersync::runtime::spawn_capacity";
+
+    /// Renders the event as a structured log entry.
+    #[must_use]
+    pub fn to_log_entry(&self) -> LogEntry {
+        let entry = match self.kind {
+            SpawnPressureKind::Raised => LogEntry::warn(Self::MESSAGE),
+            SpawnPressureKind::Cleared => LogEntry::info(Self::MESSAGE),
+        };
+        let entry = entry
+            .with_target(Self::TARGET)
+            .with_field("kind", self.kind_label())
+            .with_field("live_tasks", self.live_tasks.to_string())
+            .with_field("watermark", self.watermark.to_string());
+        match self.limit {
+            Some(limit) => entry.with_field("limit", limit.to_string()),
+            None => entry,
+        }
+    }
+
+    const fn kind_label(&self) -> &'static str {
+        match self.kind {
+            SpawnPressureKind::Raised => "raised",
+            SpawnPressureKind::Cleared => "cleared",
+        }
+    }
+
+    fn emit(&self) {
+        match self.kind {
+            SpawnPressureKind::Raised => {
+                crate::tracing_compat::warn!(
+                    live_tasks = self.live_tasks,
+                    watermark = self.watermark,
+                    limit = ?self.limit,
+                    "spawn pressure raised"
+                );
+            }
+            SpawnPressureKind::Cleared => {
+                crate::tracing_compat::info!(
+                    live_tasks = self.live_tasks,
+                    watermark = self.watermark,
+                    "spawn pressure cleared"
+                );
+            }
+        }
+    }
+}
+
+/// Point-in-time spawn admission metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpawnCapacityMetrics {
+    /// Live tasks at the last admission or completion.
+    pub live_tasks: usize,
+    /// Highest live-task count observed.
+    pub peak_live_tasks: usize,
+    /// Spawns rejected by a runtime or region live-task limit.
+    pub rejections: u64,
+    /// Spawners that had to wait for capacity.
+    pub waits: u64,
+    /// Spawners currently waiting.
+    pub waiting: usize,
+    /// Total time spawners spent waiting for capacity.
+    pub wait_time: Duration,
+    /// Watermark crossings, in both directions.
+    pub pressure_events: u64,
+}
+
+#[derive(Default)]
+struct WaitQueue {
+    next_ticket: u64,
+    waiting: VecDeque<(u64, Waker)>,
+    granted: Vec<u64>,
+    events: VecDeque<SpawnPressureEvent>,
+}
+
+impl WaitQueue {
+    fn take_grant(&mut self, ticket: u64) -> bool {
+        let Some(pos) = self.granted.iter().position(|&t| t == ticket) else {
+            return false;
+        };
+        self.granted.swap_remove(pos);
+        true
+    }
+}
+
+/// Shared live-task accounting and capacity waiters of one runtime.
+pub struct SpawnCapacityGate {
+    config: SpawnCapacityConfig,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    rejections: AtomicU64,
+    waits: AtomicU64,
+    wait_nanos: AtomicU64,
+    pressure_events: AtomicU64,
+    above_watermark: AtomicBool,
+    queue: Mutex<WaitQueue>,
+}
+
+impl fmt::Debug for SpawnCapacityGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnCapacityGate")
+            .field("config", &self.config)
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+impl Default for SpawnCapacityGate {
+    fn default() -> Self {
+        Self::new(SpawnCapacityConfig::default())
+    }
+}
+
+impl SpawnCapacityGate {
+    /// Creates a gate enforcing `config`.
+    #[must_use]
+    pub fn new(config: SpawnCapacityConfig) -> Self {
+        Self {
+            config,
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            rejections: AtomicU64::new(0),
+            waits: AtomicU64::new(0),
+            wait_nanos: AtomicU64::new(0),
+            pressure_events: AtomicU64::new(0),
+            above_watermark: AtomicBool::new(false),
+            queue: Mutex::new(WaitQueue::default()),
+        }
+    }
+
+    /// The enforced configuration.
+    #[must_use]
+    pub fn config(&self) -> &SpawnCapacityConfig {
+        &self.config
+    }
+
+    /// The runtime-wide live-task limit.
+    #[must_use]
+    pub fn limit(&self) -> Option<usize> {
+        self.config.max_live_tasks
+    }
+
+    /// Returns `true` if the gate does no accounting.
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.config.is_unlimited()
+    }
+
+    /// Current metrics.
+    #[must_use]
+    pub fn metrics(&self) -> SpawnCapacityMetrics {
+        SpawnCapacityMetrics {
+            live_tasks: self.live.load(Ordering::Acquire),
+            peak_live_tasks: self.peak.load(Ordering::Relaxed),
+            rejections: self.rejections.load(Ordering::Relaxed),
+            waits: self.waits.load(Ordering::Relaxed),
+            waiting: self.queue.lock().waiting.len(),
+            wait_time: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+            pressure_events: self.pressure_events.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drains the retained watermark crossings, oldest first.
+    ///
+    /// The most recent crossings are retained while nobody drains them.
+    pub fn take_pressure_events(&self) -> Vec<SpawnPressureEvent> {
+        self.queue.lock().events.drain(..).collect()
+    }
+
+    /// Waits until a spawn would pass the runtime-wide limit.
+    ///
+    /// Resolves immediately when the runtime is below its limit and nobody
+    /// is queued ahead; otherwise joins the FIFO queue and is released when
+    /// a task completes. Capacity is not reserved: spawn right after the
+    /// wait resolves, and wait again on [`SpawnError::AtCapacity`]. Dropping
+    /// the future leaves the queue and hands a pending release to the next
+    /// waiter, so cancelling a waiting spawner never loses a wakeup.
+    #[must_use]
+    pub fn wait(self: &Arc<Self>) -> CapacityWait {
+        CapacityWait {
+            gate: Arc::clone(self),
+            ticket: None,
+            started_at: None,
+        }
+    }
+
+    /// Returns `true` if the runtime-wide limit admits one more task.
+    pub(crate) fn has_capacity(&self) -> bool {
+        self.config
+            .max_live_tasks
+            .is_none_or(|limit| self.live.load(Ordering::Acquire) < limit)
+    }
+
+    /// Blocks the calling thread until [`wait`](Self::wait) resolves.
+    ///
+    /// On a runtime worker thread it does not block: the worker would stop
+    /// running the tasks whose completion frees capacity, so the runtime
+    /// limit is checked and [`SpawnError::AtCapacity`] returned instead.
+    pub(crate) fn block_until_available(self: &Arc<Self>) -> Result<(), SpawnError> {
+        if crate::runtime::scheduler::three_lane::current_worker_id().is_some() {
+            return self.check(self.live.load(Ordering::Acquire));
+        }
+        let waker = Arc::new(ThreadUnpark(std::thread::current()));
+        let waker = Waker::from(waker);
+        let mut task_cx = Context::from_waker(&waker);
+        let mut wait = self.wait();
+        while Pin::new(&mut wait).poll(&mut task_cx).is_pending() {
+            std::thread::park();
+        }
+        Ok(())
+    }
+
+    /// Checks the runtime-wide limit for one more task.
+    pub(crate) fn check(&self, live: usize) -> Result<(), SpawnError> {
+        match self.config.max_live_tasks {
+            Some(limit) if live >= limit => {
+                self.record_rejection();
+                Err(SpawnError::AtCapacity {
+                    scope: CapacityScope::Runtime,
+                    limit,
+                    current: live,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn record_rejection(&self) {
+        self.rejections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the live-task count after an admission or completion,
+    /// raising or clearing spawn pressure on watermark crossings.
+    pub(crate) fn observe_live(&self, live: usize) {
+        self.live.store(live, Ordering::Release);
+        self.peak.fetch_max(live, Ordering::Relaxed);
+        let Some(watermark) = self.config.soft_watermark else {
+            return;
+        };
+        let above = live >= watermark;
+        if let Some(pressure) = &self.config.pressure {
+            pressure.set_headroom(self.headroom(live, watermark));
+        }
+        if self.above_watermark.swap(above, Ordering::AcqRel) == above {
+            return;
+        }
+        let event = SpawnPressureEvent {
+            kind: if above {
+                SpawnPressureKind::Raised
+            } else {
+                SpawnPressureKind::Cleared
+            },
+            live_tasks: live,
+            watermark,
+            limit: self.config.max_live_tasks,
+        };
+        self.pressure_events.fetch_add(1, Ordering::Relaxed);
+        event.emit();
+        let mut queue = self.queue.lock();
+        if queue.events.len() == PRESSURE_EVENT_CAPACITY {
+            queue.events.pop_front();
+        }
+        queue.events.push_back(event);
+    }
+
+    /// Headroom reported while `live` tasks run: full below the watermark,
+    /// then falling linearly to zero at the hard limit (or at twice the
+    /// watermark when there is none).
+    #[allow(clippy::cast_precision_loss)]
+    fn headroom(&self, live: usize, watermark: usize) -> f32 {
+        if live < watermark {
+            return 1.0;
+        }
+        let ceiling = self
+            .config
+            .max_live_tasks
+            .unwrap_or_else(|| watermark.saturating_mul(2))
+            .max(watermark);
+        let remaining = ceiling.saturating_sub(live) as f32;
+        let span = (ceiling - watermark + 1) as f32;
+        remaining / span
+    }
+
+    /// Releases the head waiter if the runtime is below its limit.
+    ///
+    /// Called once per completed task, after the runtime-state lock is
+    /// released, so the waiter's waker never runs under it.
+    pub(crate) fn release(&self) {
+        let Some(limit) = self.config.max_live_tasks else {
+            return;
+        };
+        let waker = {
+            let mut queue = self.queue.lock();
+            if self.live.load(Ordering::Acquire) >= limit {
+                return;
+            }
+            let Some((ticket, waker)) = queue.waiting.pop_front() else {
+                return;
+            };
+            queue.granted.push(ticket);
+            waker
+        };
+        waker.wake();
+    }
+
+    /// Token whose dispatch calls [`release`](Self::release), or `None` when
+    /// no limit is configured.
+    pub(crate) fn release_token(self: &Arc<Self>) -> Option<CapacityRelease> {
+        self.config.max_live_tasks.map(|_| CapacityRelease {
+            gate: Arc::clone(self),
+        })
+    }
+}
+
+/// Deferred [`SpawnCapacityGate::release`] carried by a task completion until
+/// the runtime-state lock is released.
+pub(crate) struct CapacityRelease {
+    gate: Arc<SpawnCapacityGate>,
+}
+
+impl CapacityRelease {
+    pub(crate) fn dispatch(self) {
+        self.gate.release();
+    }
+}
+
+/// Future returned by [`SpawnCapacityGate::wait`].
+pub struct CapacityWait {
+    gate: Arc<SpawnCapacityGate>,
+    ticket: Option<u64>,
+    started_at: Option<u64>,
+}
+
+impl fmt::Debug for CapacityWait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapacityWait")
+            .field("ticket", &self.ticket)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CapacityWait {
+    fn finish(&mut self) {
+        self.ticket = None;
+        if let Some(started_at) = self.started_at.take() {
+            let waited = crate::time::wall_now().as_nanos().saturating_sub(started_at);
+            self.gate.wait_nanos.fetch_add(waited, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Future for CapacityWait {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(limit) = self.gate.config.max_live_tasks else {
+            return Poll::Ready(());
+        };
+        let gate = Arc::clone(&self.gate);
+        let mut queue = gate.queue.lock();
+        if let Some(ticket) = self.ticket {
+            if queue.take_grant(ticket) {
+                drop(queue);
+                self.finish();
+                return Poll::Ready(());
+            }
+            if let Some((_, waker)) = queue.waiting.iter_mut().find(|(t, _)| *t == ticket) {
+                waker.clone_from(cx.waker());
+            }
+            return Poll::Pending;
+        }
+        if queue.waiting.is_empty() && gate.live.load(Ordering::Acquire) < limit {
+            return Poll::Ready(());
+        }
+        let ticket = queue.next_ticket;
+        queue.next_ticket += 1;
+        queue.waiting.push_back((ticket, cx.waker().clone()));
+        drop(queue);
+        gate.waits.fetch_add(1, Ordering::Relaxed);
+        self.ticket = Some(ticket);
+        self.started_at = Some(crate::time::wall_now().as_nanos());
+        Poll::Pending
+    }
+}
+
+impl Drop for CapacityWait {
+    fn drop(&mut self) {
+        let Some(ticket) = self.ticket.take() else {
+            return;
+        };
+        let handed_over = {
+            let mut queue = self.gate.queue.lock();
+            queue.waiting.retain(|(t, _)| *t != ticket);
+            queue.take_grant(ticket)
+        };
+        if handed_over {
+            // The release meant for this waiter goes to the next one.
+            self.gate.release();
+        }
+    }
+}
+
+struct ThreadUnpark(std::thread::Thread);
+
+impl std::task::Wake for ThreadUnpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn counting_waker() -> (Arc<CountingWaker>, Waker) {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&counter));
+        (counter, waker)
+    }
+
+    fn poll_wait(wait: &mut CapacityWait, waker: &Waker) -> Poll<()> {
+        Pin::new(wait).poll(&mut Context::from_waker(waker))
+    }
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    #[test]
+    fn unlimited_gate_never_waits() {
+        init_test("unlimited_gate_never_waits");
+        let gate = Arc::new(SpawnCapacityGate::default());
+        assert!(gate.is_unlimited());
+        assert!(gate.check(usize::MAX - 1).is_ok());
+        let mut wait = gate.wait();
+        assert!(poll_wait(&mut wait, Waker::noop()).is_ready());
+        assert_eq!(gate.metrics(), SpawnCapacityMetrics::default());
+        crate::test_complete!("unlimited_gate_never_waits");
+    }
+
+    #[test]
+    fn hard_limit_rejects_with_runtime_scope() {
+        init_test("hard_limit_rejects_with_runtime_scope");
+        let gate = SpawnCapacityGate::new(SpawnCapacityConfig::default().with_max_live_tasks(2));
+        assert!(gate.check(1).is_ok());
+        let err = gate.check(2).expect_err("at limit");
+        assert_eq!(
+            err,
+            SpawnError::AtCapacity {
+                scope: CapacityScope::Runtime,
+                limit: 2,
+                current: 2,
+            }
+        );
+        assert_eq!(err.code(), "ASUP-E010");
+        assert_eq!(gate.metrics().rejections, 1);
+        crate::test_complete!("hard_limit_rejects_with_runtime_scope");
+    }
+
+    #[test]
+    fn waiters_are_released_fifo_one_per_completion() {
+        init_test("waiters_are_released_fifo_one_per_completion");
+        let gate = Arc::new(SpawnCapacityGate::new(
+            SpawnCapacityConfig::default().with_max_live_tasks(1),
+        ));
+        gate.observe_live(1);
+
+        let (first_count, first_waker) = counting_waker();
+        let (second_count, second_waker) = counting_waker();
+        let mut first = gate.wait();
+        let mut second = gate.wait();
+        assert!(poll_wait(&mut first, &first_waker).is_pending());
+        assert!(poll_wait(&mut second, &second_waker).is_pending());
+        assert_eq!(gate.metrics().waiting, 2);
+
+        // Capacity freed: only the head waiter is released.
+        gate.observe_live(0);
+        gate.release();
+        assert_eq!(first_count.0.load(Ordering::SeqCst), 1);
+        assert_eq!(second_count.0.load(Ordering::SeqCst), 0);
+        assert!(poll_wait(&mut first, &first_waker).is_ready());
+        assert!(poll_wait(&mut second, &second_waker).is_pending());
+
+        // A newcomer queues behind the remaining waiter.
+        let mut late = gate.wait();
+        assert!(poll_wait(&mut late, Waker::noop()).is_pending());
+
+        // The first waiter spawns and its task completes.
+        gate.observe_live(1);
+        gate.release();
+        assert_eq!(second_count.0.load(Ordering::SeqCst), 0);
+        gate.observe_live(0);
+        gate.release();
+        assert_eq!(second_count.0.load(Ordering::SeqCst), 1);
+        assert!(poll_wait(&mut second, &second_waker).is_ready());
+        assert!(poll_wait(&mut late, Waker::noop()).is_pending());
+
+        let metrics = gate.metrics();
+        assert_eq!(metrics.waits, 3);
+        assert_eq!(metrics.waiting, 1);
+        crate::test_complete!("waiters_are_released_fifo_one_per_completion");
+    }
+
+    #[test]
+    fn dropped_waiter_hands_its_release_to_the_next() {
+        init_test("dropped_waiter_hands_its_release_to_the_next");
+        let gate = Arc::new(SpawnCapacityGate::new(
+            SpawnCapacityConfig::default().with_max_live_tasks(1),
+        ));
+        gate.observe_live(1);
+        let (_, first_waker) = counting_waker();
+        let (second_count, second_waker) = counting_waker();
+        let mut first = gate.wait();
+        let mut second = gate.wait();
+        assert!(poll_wait(&mut first, &first_waker).is_pending());
+        assert!(poll_wait(&mut second, &second_waker).is_pending());
+
+        gate.observe_live(0);
+        gate.release();
+        // Cancelled after being granted, before it could spawn.
+        drop(first);
+        assert_eq!(second_count.0.load(Ordering::SeqCst), 1);
+        assert!(poll_wait(&mut second, &second_waker).is_ready());
+        assert_eq!(gate.metrics().waiting, 0);
+        crate::test_complete!("dropped_waiter_hands_its_release_to_the_next");
+    }
+
+    #[test]
+    fn watermark_crossings_emit_events_and_feed_pressure() {
+        init_test("watermark_crossings_emit_events_and_feed_pressure");
+        let pressure = Arc::new(SystemPressure::new());
+        let gate = SpawnCapacityGate::new(
+            SpawnCapacityConfig::default()
+                .with_max_live_tasks(4)
+                .with_soft_watermark(2)
+                .with_pressure(Arc::clone(&pressure)),
+        );
+
+        gate.observe_live(1);
+        assert!(gate.take_pressure_events().is_empty());
+        assert!((pressure.headroom() - 1.0).abs() < f32::EPSILON);
+
+        gate.observe_live(2);
+        gate.observe_live(3);
+        let raised = gate.take_pressure_events();
+        assert_eq!(
+            raised,
+            vec![SpawnPressureEvent {
+                kind: SpawnPressureKind::Raised,
+                live_tasks: 2,
+                watermark: 2,
+                limit: Some(4),
+            }]
+        );
+        assert!(pressure.headroom() < 0.5);
+        gate.observe_live(4);
+        assert!(pressure.headroom().abs() < f32::EPSILON);
+
+        gate.observe_live(1);
+        let cleared = gate.take_pressure_events();
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].kind, SpawnPressureKind::Cleared);
+        assert!((pressure.headroom() - 1.0).abs() < f32::EPSILON);
+
+        let entry = raised[0].to_log_entry();
+        assert_eq!(entry.target(), Some(SpawnPressureEvent::TARGET));
+        assert_eq!(gate.metrics().pressure_events, 2);
+        assert_eq!(gate.metrics().peak_live_tasks, 4);
+        crate::test_complete!("watermark_crossings_emit_events_and_feed_pressure");
+    }
+}
@@ -3372,6 +3372,162 @@ mod tests {
         assert_eq!(grandchild_ran.load(Ordering::SeqCst), 1);
     }
 
+    /// Capability restrictions compose across three generations of
+    /// `Cx::spawn`: each generation can only narrow, widening is refused,
+    /// and every denial names the outermost site that removed the
+    /// capability.
+    #[test]
+    fn cx_spawn_capability_restrictions_compose_across_generations() {
+        use crate::cx::{Capability, CapabilitySet};
+
+        let (mut lab, parent_cx, _root) = lab_with_parent_cx();
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let observed_in_child = Arc::clone(&observed);
+
+        let gen1 = parent_cx
+            .restrict_capabilities(CapabilitySet::all().without(Capability::Process), "gen1")
+            .expect("narrowing succeeds");
+        let _child = gen1
+            .spawn(move |child| async move {
+                let gen2 = child
+                    .restrict_capabilities(
+                        CapabilitySet::all()
+                            .without(Capability::Process)
+                            .without(Capability::Fs),
+                        "gen2",
+                    )
+                    .expect("narrowing succeeds");
+                gen2.spawn(move |grandchild| async move {
+                    let widened = grandchild
+                        .restrict_capabilities(CapabilitySet::all(), "widen")
+                        .expect_err("widening is refused");
+                    assert!(widened.is_widening());
+                    let gen3 = grandchild
+                        .restrict_capabilities(CapabilitySet::none(), "gen3")
+                        .expect("narrowing succeeds");
+                    gen3.spawn(move |great| async move {
+                        let restriction = great.capability_restriction().expect("inherited");
+                        let mut observed = observed_in_child.lock().unwrap();
+                        observed.push(restriction.depth().to_string());
+                        for capability in [Capability::Process, Capability::Fs, Capability::Net] {
+                            let denied = great.check_capability(capability).expect_err("denied");
+                            observed.push(format!("{capability}:{}", denied.site()));
+                        }
+                        let ambient =
+                            crate::cx::attenuation::check_ambient_capability(Capability::Net)
+                                .expect_err("ambient lookups observe the task's restriction");
+                        observed.push(format!("ambient:{}", ambient.site()));
+                    })
+                    .expect("great-grandchild spawn");
+                })
+                .expect("grandchild spawn");
+            })
+            .expect("child spawn");
+        lab.run_until_quiescent();
+
+        assert_eq!(
+            *observed.lock().unwrap(),
+            ["3", "process:gen1", "fs:gen2", "net:gen3", "ambient:gen3"]
+        );
+        assert!(parent_cx.capability_restriction().is_none());
+    }
+
+    /// The restriction a task inherits is recorded on its task record.
+    #[test]
+    fn cx_spawn_records_inherited_restriction_on_task_record() {
+        use crate::cx::{Capability, CapabilitySet};
+
+        let (mut lab, parent_cx, _root) = lab_with_parent_cx();
+        let child_id = Arc::new(Mutex::new(None));
+        let child_id_in_child = Arc::clone(&child_id);
+        let release = Arc::new(AtomicBool::new(false));
+        let release_in_child = Arc::clone(&release);
+
+        let restricted = parent_cx
+            .restrict_capabilities(CapabilitySet::none().with(Capability::Net), "plugin:audit")
+            .expect("narrowing succeeds");
+        let _handle = restricted
+            .spawn(move |child| async move {
+                *child_id_in_child.lock().unwrap() = Some(child.task_id());
+                while !release_in_child.load(Ordering::SeqCst) {
+                    crate::runtime::yield_now().await;
+                }
+            })
+            .expect("child spawn");
+
+        let mut recorded = None;
+        for _ in 0..200 {
+            lab.step_for_test();
+            let id = *child_id.lock().unwrap();
+            if let Some(id) = id {
+                recorded = lab
+                    .state
+                    .task(id)
+                    .and_then(crate::record::TaskRecord::capability_restriction);
+                break;
+            }
+        }
+        let recorded = recorded.expect("task record carries the inherited restriction");
+        assert_eq!(recorded.site(), "plugin:audit");
+        assert_eq!(
+            recorded.allowed(),
+            CapabilitySet::none().with(Capability::Net)
+        );
+
+        release.store(true, Ordering::SeqCst);
+        lab.run_until_quiescent();
+    }
+
+    /// Restricted contexts refuse blocking-pool and cross-region spawns with
+    /// a typed error before anything is enqueued.
+    #[test]
+    fn cx_spawn_restricted_blocking_and_cross_region_are_denied() {
+        use crate::cx::{Capability, CapabilitySet};
+
+        let (mut lab, parent_cx, root) = lab_with_parent_cx();
+        let restricted = parent_cx
+            .restrict_capabilities(CapabilitySet::none(), "sandbox")
+            .expect("narrowing succeeds");
+
+        let blocking = restricted.spawn_blocking(|_child| 1usize);
+        assert!(matches!(
+            blocking,
+            Err(SpawnError::CapabilityDenied(ref denied))
+                if denied.capability() == Capability::BlockingPool && denied.site() == "sandbox"
+        ));
+
+        let child_region = lab
+            .state
+            .create_child_region(root, Budget::INFINITE)
+            .expect("child region");
+        let scope: crate::cx::Scope<'static> =
+            crate::cx::Scope::new(child_region, Budget::INFINITE).with_pending_spawn_counter(
+                lab.state
+                    .region(child_region)
+                    .map(crate::record::RegionRecord::pending_spawn_handle),
+            );
+        let cross = restricted.spawn_in(&scope, |_child| async move { 1usize });
+        assert!(matches!(
+            cross,
+            Err(SpawnError::CapabilityDenied(ref denied))
+                if denied.capability() == Capability::CrossRegionSpawn
+        ));
+        assert_eq!(
+            cross.unwrap_err().code(),
+            "ASUP-E009",
+            "capability denials carry their registry code"
+        );
+
+        let same_region = restricted
+            .spawn(|_child| async move { 2usize })
+            .expect("own-region spawn stays allowed");
+        let allowed_cross = parent_cx
+            .spawn_in(&scope, |_child| async move { 3usize })
+            .expect("unrestricted cross-region spawn");
+        lab.run_until_quiescent();
+        drop((same_region, allowed_cross));
+    }
+
     /// A Cx built without runtime wiring (test-internals constructor) has
     /// no gateway: spawn errs with RuntimeUnavailable and never panics.
     #[test]
@@ -16,6 +16,9 @@ use crate::cx::scope::{CatchUnwind, payload_to_string};
 use crate::epoch::EpochId;
 use crate::error::{Error, ErrorKind};
 use crate::observability::metrics::{MetricsProvider, NoOpMetrics, OutcomeKind};
+use crate::observability::region_events::{
+    RegionCloseStatus, RegionEventDetail, RegionEventFilter, RegionEventHub, RegionEventStream,
+};
 use crate::observability::swarm_pressure_governor::{
     SwarmPressureGovernor, SwarmPressureGovernorConfig,
 };
@@ -35,6 +38,8 @@ use crate::runtime::reactor::Reactor;
 use crate::runtime::resource_monitor::{
     DegradationLevel, DegradationStatsSnapshot, MonitorConfig, RegionPriority, ResourceMonitor,
 };
+use crate::runtime::shutdown_report::{ShutdownRecorder, ShutdownReport, ShutdownTaskOutcome};
+use crate::runtime::spawn_capacity::{CapacityScope, SpawnCapacityConfig, SpawnCapacityGate};
 use crate::runtime::stored_task::{LocalStoredTask, StoredTask};
 use crate::runtime::task_handle::JoinError;
 use crate::runtime::{BlockingPoolHandle, ObligationTable, RegionTable, TaskTable};
@@ -49,7 +54,8 @@ use crate::types::task_context::{
 };
 use crate::types::{
     Budget, CancelAttributionConfig, CancelKind, CancelReason, CapabilityBudget,
-    CapabilityBudgetRequirements, ObligationId, Outcome, RegionId, TaskId, Time,
+    CapabilityBudgetRequirements, IdKind, IdRef, ObligationId, Outcome, RegionId, Severity,
+    StaleId, TaskId, Time,
     id::{next_bootstrap_region_id, next_bootstrap_task_id},
 };
 use crate::util::{Arena, ArenaIndex, DetEntropy, EntropySource, OsEntropy};
@@ -589,13 +595,16 @@ impl Drop for TaskCompletionRetirements {
 /// attempted more than once through this API. Abandoning the token may happen
 /// beneath a caller-owned outer runtime-state lock, so its [`Drop`]
 /// implementation deliberately leaks an undispatched payload rather than
-/// running an arbitrary metrics-provider destructor there.
+/// running an arbitrary metrics-provider destructor there. Pending exit
+/// signals are not delivered from `Drop`; they are dropped.
 #[must_use = "task completion observers must be dispatched after waiter publication"]
 pub struct TaskCompletionObserver {
     payload: Option<TaskCompletionObserverPayload>,
     panic_count: Option<Arc<AtomicU64>>,
     retired_cancel_wakers: TaskCompletionRetirements,
     epoch_telemetry: Option<super::epoch_tracker::EpochTelemetryDispatch>,
+    capacity_release: Option<super::spawn_capacity::CapacityRelease>,
+    exit_deliveries: crate::link::ExitDeliveries,
 }
 
 enum TaskCompletionObserverPayload {
@@ -637,6 +646,8 @@ impl TaskCompletionObserver {
             panic_count: Some(Arc::clone(panic_count)),
             retired_cancel_wakers: TaskCompletionRetirements::empty(),
             epoch_telemetry: None,
+            capacity_release: None,
+            exit_deliveries: crate::link::ExitDeliveries::default(),
         }
     }
 
@@ -646,6 +657,8 @@ impl TaskCompletionObserver {
             panic_count: Some(Arc::clone(panic_count)),
             retired_cancel_wakers: TaskCompletionRetirements::empty(),
             epoch_telemetry: None,
+            capacity_release: None,
+            exit_deliveries: crate::link::ExitDeliveries::default(),
         }
     }
 
@@ -656,6 +669,18 @@ impl TaskCompletionObserver {
         }
     }
 
+    fn attach_capacity_release(
+        &mut self,
+        release: Option<super::spawn_capacity::CapacityRelease>,
+    ) {
+        debug_assert!(self.capacity_release.is_none());
+        self.capacity_release = release;
+    }
+
+    fn attach_exit_deliveries(&mut self, deliveries: crate::link::ExitDeliveries) {
+        self.exit_deliveries.append(deliveries);
+    }
+
     /// Runs observer delivery and final metrics-provider retirement behind
     /// separate unwind boundaries. If the metrics callback panics, tracing is
     /// intentionally skipped rather than retrying either callback and risking
@@ -664,6 +689,14 @@ impl TaskCompletionObserver {
     /// caught panic increments this runtime's callback-free atomic failure
     /// counter once for this dispatch without invoking another observer.
     pub fn dispatch(mut self) {
+        // Spawners waiting for live-task capacity are woken here, outside the
+        // runtime-state lock, whatever the observer payload.
+        if let Some(release) = self.capacity_release.take() {
+            release.dispatch();
+        }
+        // Trapped exit signals from the task's links likewise reach their
+        // mailboxes only now.
+        std::mem::take(&mut self.exit_deliveries).dispatch();
         let Some(panic_count) = self.panic_count.take() else {
             return;
         };
@@ -748,6 +781,10 @@ impl TaskCompletionObserver {
 
 impl Drop for TaskCompletionObserver {
     fn drop(&mut self) {
+        // Undelivered exit signals are dropped with the token. Each mailbox is
+        // a clone of the one registered in the link set, so dropping it only
+        // releases a reference; it does not close the receiver's channel.
+        drop(std::mem::take(&mut self.exit_deliveries));
         let Some(payload) = self.payload.take() else {
             return;
         };
@@ -1062,6 +1099,19 @@ pub enum SpawnError {
         /// Provisional mailbox identity of the rejected request.
         task_id: TaskId,
     },
+    /// The spawning context's capability restriction withholds the
+    /// capability this spawn needs (blocking pool or cross-region spawn).
+    CapabilityDenied(crate::cx::CapabilityDenied),
+    /// The runtime, or a region's subtree, has reached its live-task
+    /// limit.
+    AtCapacity {
+        /// The limit that rejected the spawn.
+        scope: CapacityScope,
+        /// The configured live-task limit.
+        limit: usize,
+        /// The number of live tasks in that scope at the time of rejection.
+        current: usize,
+    },
 }
 
 impl SpawnError {
@@ -1085,6 +1135,8 @@ impl SpawnError {
             Self::RegionAtCapacity { .. } => "ASUP-E006",
             Self::AuthorizationDenied { .. } => "ASUP-E007",
             Self::AdmissionSlotAlreadyReserved { .. } => "ASUP-E008",
+            Self::CapabilityDenied(_) => "ASUP-E009",
+            Self::AtCapacity { .. } => "ASUP-E010",
         }
     }
 }
@@ -1146,12 +1198,35 @@ impl std::fmt::Display for SpawnError {
                  each spawn request requires a fresh AdmittedTaskSlot; create the slot \
                  alongside its TaskHandle and never reuse it for another request"
             ),
+            Self::CapabilityDenied(denied) => write!(
+                f,
+                "[ASUP-E009] {denied} — the spawning Cx was derived with \
+                 restrict_capabilities and withholds this capability; spawn from a \
+                 context that still holds it, or widen the restriction at its site"
+            ),
+            Self::AtCapacity {
+                scope,
+                limit,
+                current,
+            } => write!(
+                f,
+                "[ASUP-E010] live-task limit reached: scope={scope} limit={limit} \
+                 current={current} — back-pressure point: await \
+                 SpawnCapacityGate::wait before retrying, or raise the runtime's or \
+                 region's max_live_tasks if the capacity was misconfigured"
+            ),
         }
     }
 }
 
 impl std::error::Error for SpawnError {}
 
+impl From<crate::cx::CapabilityDenied> for SpawnError {
+    fn from(denied: crate::cx::CapabilityDenied) -> Self {
+        Self::CapabilityDenied(denied)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum TaskCompletionKind {
     Ok,
@@ -1172,6 +1247,26 @@ impl TaskCompletionKind {
         }
     }
 
+    const fn severity(self) -> Option<Severity> {
+        match self {
+            Self::Ok => Some(Severity::Ok),
+            Self::Err => Some(Severity::Err),
+            Self::Cancelled => Some(Severity::Cancelled),
+            Self::Panicked => Some(Severity::Panicked),
+            Self::Unknown => None,
+        }
+    }
+
+    const fn shutdown_outcome(self) -> ShutdownTaskOutcome {
+        match self {
+            Self::Ok => ShutdownTaskOutcome::Success,
+            Self::Err => ShutdownTaskOutcome::Failed,
+            Self::Cancelled => ShutdownTaskOutcome::Cancelled,
+            Self::Panicked => ShutdownTaskOutcome::Panicked,
+            Self::Unknown => ShutdownTaskOutcome::Pending,
+        }
+    }
+
     const fn as_str(self) -> &'static str {
         match self {
             Self::Ok => "ok",
@@ -1400,6 +1495,13 @@ pub struct RuntimeState {
     timer_driver: Option<TimerDriverHandle>,
     /// Logical clock mode used for task contexts.
     logical_clock_mode: LogicalClockMode,
+    /// Overshoot a `timeout()` may have past the ambient deadline before its
+    /// clamp is logged (see [`crate::cx::Cx::deadline_clamp_slack`]).
+    deadline_clamp_slack: Duration,
+    /// Capability-usage audit attached to new task contexts.
+    capability_audit: Option<Arc<crate::cx::CapabilityAudit>>,
+    /// Live-task admission limits and capacity waiters.
+    spawn_capacity: Arc<SpawnCapacityGate>,
     /// Cancel attribution configuration (cause-chain limits, memory caps).
     cancel_attribution: CancelAttributionConfig,
     /// Entropy source for capability-based randomness.
@@ -1488,6 +1590,10 @@ pub struct RuntimeState {
     finalizer_history: Vec<FinalizerHistoryEvent>,
     /// Append-only loser-drain evidence for post-run oracle hydration.
     loser_drain_history: LoserDrainHistoryHandle,
+    /// Live supervisors and their children, for tree export.
+    supervision_tree: crate::supervision::SupervisionTree,
+    /// Links between tasks, resolved into exit signals as tasks complete.
+    links: crate::link::LinkSet,
     /// Monotonic id source for finalizer registrations.
     next_finalizer_id: u64,
     /// Per-module epoch cursors feeding the runtime epoch tracker.
@@ -1519,6 +1625,11 @@ pub struct RuntimeState {
     /// This violates the quiescence invariant. We defer region state advancement
     /// until after leak handling completes to prevent reentrancy.
     deferred_region_advancements: HashSet<RegionId>,
+    /// Shutdown transition recorder, armed only while a shutdown report is
+    /// being collected.
+    shutdown_recorder: Option<Box<super::shutdown_report::ShutdownRecorder>>,
+    /// Fan-out point for region lifecycle events.
+    region_events: Arc<RegionEventHub>,
 }
 
 impl std::fmt::Debug for RuntimeState {
@@ -1543,6 +1654,9 @@ impl std::fmt::Debug for RuntimeState {
             .field("io_driver", &self.io_driver)
             .field("timer_driver", &self.timer_driver)
             .field("logical_clock_mode", &self.logical_clock_mode)
+            .field("deadline_clamp_slack", &self.deadline_clamp_slack)
+            .field("capability_audit", &self.capability_audit)
+            .field("spawn_capacity", &self.spawn_capacity)
             .field("cancel_attribution", &self.cancel_attribution)
             .field("entropy_source", &"<dyn EntropySource>")
             .field(
@@ -1591,6 +1705,7 @@ impl std::fmt::Debug for RuntimeState {
                 "loser_drain_history_len",
                 &self.loser_drain_history.snapshot().len(),
             )
+            .field("link_count", &self.links.len())
             .field("next_finalizer_id", &self.next_finalizer_id)
             .field("region_table_epoch", &self.region_table_epoch)
             .field("task_table_epoch", &self.task_table_epoch)
@@ -1635,6 +1750,9 @@ impl RuntimeState {
             io_driver: None,
             timer_driver: None,
             logical_clock_mode: LogicalClockMode::Lamport,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
+            capability_audit: None,
+            spawn_capacity: Arc::new(SpawnCapacityGate::default()),
             cancel_attribution: CancelAttributionConfig::default(),
             entropy_source: Arc::new(OsEntropy),
             spawn_authorization_key: None,
@@ -1663,6 +1781,8 @@ impl RuntimeState {
             active_manual_finalizers: HashMap::new(),
             finalizer_history: Vec::new(),
             loser_drain_history: LoserDrainHistoryRecorder::new_handle(),
+            supervision_tree: crate::supervision::SupervisionTree::default(),
+            links: crate::link::LinkSet::new(),
             next_finalizer_id: 0,
             region_table_epoch: EpochId::GENESIS,
             task_table_epoch: EpochId::GENESIS,
@@ -1678,6 +1798,8 @@ impl RuntimeState {
             resource_monitor,
             swarm_pressure_governor,
             deferred_region_advancements: HashSet::new(),
+            shutdown_recorder: None,
+            region_events: Arc::new(RegionEventHub::new()),
         }
     }
 
@@ -1869,7 +1991,7 @@ impl RuntimeState {
     }
 
     #[inline]
-    fn current_runtime_time(&self) -> Time {
+    pub(crate) fn current_runtime_time(&self) -> Time {
         self.timer_driver
             .as_ref()
             .map_or(self.now, TimerDriverHandle::now)
@@ -2063,6 +2185,61 @@ impl RuntimeState {
         self.logical_clock_mode = mode;
     }
 
+    /// Returns the deadline clamp warning slack for new task contexts.
+    #[must_use]
+    pub fn deadline_clamp_slack(&self) -> Duration {
+        self.deadline_clamp_slack
+    }
+
+    /// Sets the deadline clamp warning slack for new task contexts.
+    pub fn set_deadline_clamp_slack(&mut self, slack: Duration) {
+        self.deadline_clamp_slack = slack;
+    }
+
+    /// Returns the capability-usage audit attached to new task contexts.
+    #[must_use]
+    pub fn capability_audit(&self) -> Option<&Arc<crate::cx::CapabilityAudit>> {
+        self.capability_audit.as_ref()
+    }
+
+    /// Sets the capability-usage audit attached to new task contexts.
+    pub fn set_capability_audit(&mut self, audit: Option<Arc<crate::cx::CapabilityAudit>>) {
+        self.capability_audit = audit;
+    }
+
+    /// Returns the gate enforcing this runtime's live-task limits.
+    #[must_use]
+    pub fn spawn_capacity(&self) -> &Arc<SpawnCapacityGate> {
+        &self.spawn_capacity
+    }
+
+    /// Returns `true` if one more task fits under the runtime live-task
+    /// limit.
+    ///
+    /// When it does not, the gate is resynchronised with the live count so
+    /// a following [`SpawnCapacityGate::wait`] queues rather than resolving
+    /// at once.
+    pub(crate) fn has_spawn_capacity(&self) -> bool {
+        let Some(limit) = self.spawn_capacity.limit() else {
+            return true;
+        };
+        let live = self.live_task_count();
+        if live < limit {
+            return true;
+        }
+        self.spawn_capacity.observe_live(live);
+        false
+    }
+
+    /// Replaces the live-task admission limits.
+    ///
+    /// Spawners already waiting on the previous gate are not carried over,
+    /// so configure limits before spawning.
+    pub fn set_spawn_capacity(&mut self, config: SpawnCapacityConfig) {
+        self.spawn_capacity = Arc::new(SpawnCapacityGate::new(config));
+        self.observe_spawn_capacity();
+    }
+
     /// Returns the cancel attribution configuration for this runtime.
     #[must_use]
     pub fn cancel_attribution_config(&self) -> CancelAttributionConfig {
@@ -2091,6 +2268,12 @@ impl RuntimeState {
         self.observability = Some(RuntimeObservability::new(config));
     }
 
+    /// Returns the log collector shared by tasks, if observability is configured.
+    #[must_use]
+    pub fn log_collector(&self) -> Option<LogCollector> {
+        self.observability.as_ref().map(|obs| obs.collector.clone())
+    }
+
     /// Clears runtime observability configuration.
     pub fn clear_observability_config(&mut self) {
         self.observability = None;
@@ -2409,6 +2592,32 @@ impl RuntimeState {
         CancellationEffects::new(changed && publication.is_published(), wakes)
     }
 
+    /// Requests cancellation of a task named by an externally supplied id.
+    ///
+    /// [`cancel_task`](Self::cancel_task) treats an unknown id as a no-op.
+    /// Remote and operator cancel paths use this instead: a stale id is
+    /// rejected with [`StaleId`] and logged, so a cancel aimed at a task that
+    /// has exited is visible rather than silently dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if `task_id` no longer names a live task.
+    pub fn cancel_task_checked(
+        &mut self,
+        task_id: TaskId,
+        reason: &CancelReason,
+    ) -> Result<CancellationEffects<bool>, StaleId> {
+        if let Err(stale) = self.checked_task(task_id) {
+            crate::tracing_compat::warn!(
+                task_id = ?task_id,
+                stale = %stale,
+                "rejected cancellation for stale task id"
+            );
+            return Err(stale);
+        }
+        Ok(self.cancel_task(task_id, reason))
+    }
+
     /// Applies a task-handle cancellation command and returns the effective
     /// cancel-lane priority captured with the initial-publication decision.
     ///
@@ -2592,6 +2801,30 @@ impl RuntimeState {
         removed
     }
 
+    /// Resizes the runtime's object pools from `config`.
+    ///
+    /// Called once while the runtime is being built, before any spawn.
+    pub(crate) fn configure_object_pools(
+        &mut self,
+        config: &super::object_pool::ObjectPoolConfig,
+        worker_threads: usize,
+    ) {
+        self.tasks.configure_object_pools(config, worker_threads);
+    }
+
+    /// Releases every pooled object back to the allocator.
+    ///
+    /// Returns the number of objects drained.
+    pub fn drain_object_pools(&mut self) -> usize {
+        self.tasks.drain_task_record_pool()
+    }
+
+    /// Returns the task-record pool's cumulative hit/miss/recycle counters.
+    #[must_use]
+    pub fn task_record_pool_stats(&self) -> super::object_pool::ObjectPoolStats {
+        self.tasks.task_record_object_pool_stats()
+    }
+
     /// Removes a task record from the arena and recycles it into the pool.
     #[inline]
     pub fn recycle_task(&mut self, task_id: TaskId) {
@@ -2710,6 +2943,64 @@ impl RuntimeState {
         self.obligations.get_mut(obligation_id.arena_index())
     }
 
+    /// Returns the task record for `task_id`, telling a stale id (its task
+    /// exited, or the slot now holds a newer task) apart from a live one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if `task_id` no longer names a live task.
+    pub fn checked_task(&self, task_id: TaskId) -> Result<&TaskRecord, StaleId> {
+        let index = task_id.arena_index();
+        self.tasks.get(index).ok_or_else(|| {
+            StaleId::new(IdKind::Task, index, self.tasks.occupant_generation(index.index()))
+        })
+    }
+
+    /// Returns the region record for `region_id`, rejecting stale ids.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if `region_id` no longer names a live region.
+    pub fn checked_region(&self, region_id: RegionId) -> Result<&RegionRecord, StaleId> {
+        let index = region_id.arena_index();
+        self.regions.get(index).ok_or_else(|| {
+            StaleId::new(IdKind::Region, index, self.regions.occupant_generation(index.index()))
+        })
+    }
+
+    /// Returns the obligation record for `obligation_id`, rejecting stale ids.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if `obligation_id` no longer names a live
+    /// obligation.
+    pub fn checked_obligation(
+        &self,
+        obligation_id: ObligationId,
+    ) -> Result<&ObligationRecord, StaleId> {
+        let index = obligation_id.arena_index();
+        self.obligations.get(index).ok_or_else(|| {
+            let current = self.obligations.occupant_generation(index.index());
+            StaleId::new(IdKind::Obligation, index, current)
+        })
+    }
+
+    /// Resolves a parsed [`IdRef`] (from a dashboard query, a dump, or a
+    /// protocol message) to the arena index of the live entity it names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if the slot is vacant or, for a reference that
+    /// carries a generation, holds a different one.
+    pub fn resolve_id_ref(&self, id: IdRef) -> Result<ArenaIndex, StaleId> {
+        let current = match id.kind() {
+            IdKind::Region => self.regions.occupant_generation(id.index()),
+            IdKind::Task => self.tasks.occupant_generation(id.index()),
+            IdKind::Obligation => self.obligations.occupant_generation(id.index()),
+        };
+        id.resolve(current)
+    }
+
     /// Returns an iterator over all obligation records.
     pub fn obligations_iter(&self) -> impl Iterator<Item = (ArenaIndex, &ObligationRecord)> {
         self.obligations.iter()
@@ -2821,6 +3112,7 @@ impl RuntimeState {
         self.root_region = Some(id);
         self.record_trace_event(|seq| TraceEvent::region_created(seq, now, id, None));
         self.metrics.region_created(id, None);
+        self.note_region_created(id, None, now);
 
         // Notify epoch tracker of region creation
         self.notify_runtime_epoch_advance(super::epoch_tracker::ModuleId::RegionTable);
@@ -2888,6 +3180,46 @@ impl RuntimeState {
         capability_budget: CapabilityBudget,
         requirements: CapabilityBudgetRequirements,
         priority: RegionPriority,
+    ) -> Result<RegionId, RegionCreateError> {
+        self.create_child_region_with_label(
+            parent,
+            budget,
+            capability_budget,
+            requirements,
+            priority,
+            None,
+        )
+    }
+
+    /// Creates a child region carrying a class label.
+    ///
+    /// The label is reported with every lifecycle event of the region, so
+    /// [`RegionEventFilter::with_label_prefix`] subscribers see it from the
+    /// created event onward.
+    pub fn create_labeled_child_region(
+        &mut self,
+        parent: RegionId,
+        budget: Budget,
+        label: impl Into<Arc<str>>,
+    ) -> Result<RegionId, RegionCreateError> {
+        self.create_child_region_with_label(
+            parent,
+            budget,
+            CapabilityBudget::UNSPECIFIED,
+            CapabilityBudgetRequirements::NONE,
+            RegionPriority::Normal,
+            Some(label.into()),
+        )
+    }
+
+    pub(crate) fn create_child_region_with_label(
+        &mut self,
+        parent: RegionId,
+        budget: Budget,
+        capability_budget: CapabilityBudget,
+        requirements: CapabilityBudgetRequirements,
+        priority: RegionPriority,
+        label: Option<Arc<str>>,
     ) -> Result<RegionId, RegionCreateError> {
         self.check_resource_pressure_for_region(priority)?;
 
@@ -2904,8 +3236,15 @@ impl RuntimeState {
             .set_region_priority(id, priority);
         self.track_new_region_in_cancel_protocol_validator(id, Some(parent), now);
 
+        if label.is_some()
+            && let Some(region) = self.regions.get(id.arena_index())
+        {
+            region.set_label(label);
+        }
+
         self.record_trace_event(|seq| TraceEvent::region_created(seq, now, id, Some(parent)));
         self.metrics.region_created(id, Some(parent));
+        self.note_region_created(id, Some(parent), now);
 
         // Register resource envelope with swarm pressure governor
         if let Ok(envelope) =
@@ -3017,6 +3356,12 @@ impl RuntimeState {
 
         use crate::channel::oneshot;
 
+        // Finalizers must run whatever the load; everything else is bounded
+        // by the runtime and region-subtree live-task limits.
+        if !cleanup_task {
+            self.check_spawn_capacity(region)?;
+        }
+
         // Create oneshot channel for the result
         let (result_tx, result_rx) =
             oneshot::channel::<Result<T, crate::runtime::task_handle::JoinError>>();
@@ -3096,6 +3441,8 @@ impl RuntimeState {
             self.recycle_task(task_id);
             return Err(SpawnError::RegionNotFound(region));
         }
+        self.observe_spawn_capacity();
+        self.note_region_task_spawned(region, task_id, now);
 
         // Create the task's capability context
         let entropy = self.entropy_source.fork(task_id);
@@ -3118,11 +3465,18 @@ impl RuntimeState {
         )
         .with_blocking_pool_handle(self.blocking_pool_handle())
         .with_logical_clock(logical_clock)
+        .with_deadline_clamp_slack(self.deadline_clamp_slack)
+        .with_capability_audit(self.capability_audit.clone())
         .with_spawn_gateway(self.spawn_gateway.clone())
         .with_pending_spawn_counter(
             self.regions
                 .get(region.arena_index())
                 .map(crate::record::RegionRecord::pending_spawn_handle),
+        )
+        .with_disposal_slot(
+            self.regions
+                .get(region.arena_index())
+                .map(crate::record::RegionRecord::disposal_slot),
         );
         cx.set_trace_buffer(self.trace_handle());
         cx.set_loser_drain_history_handle(self.loser_drain_history_handle());
@@ -3186,6 +3540,7 @@ impl RuntimeState {
         let system_cx = self.create_system_cx();
         let (task_id, handle, cx, result_tx, spawn_effects) =
             self.create_task_infrastructure(&system_cx, region, budget, false)?;
+        let completion = handle.completion_guard();
 
         // Wrap the future to send the result through the channel. Panics must
         // surface as `JoinError::Panicked` rather than silently closing the
@@ -3194,20 +3549,19 @@ impl RuntimeState {
             // This legacy state-threaded API does not own a scheduler lane.
             // First poll proves the stored task was published and runs outside
             // the caller's runtime-state lock.
+            completion.start();
             spawn_effects.dispatch();
             match (CatchUnwind { inner: future }).await {
                 Ok(result) => {
-                    let _ = result_tx.send(&cx, Ok::<_, JoinError>(result));
+                    let _ = result_tx.send(&cx, completion.finish(Ok(result)));
                     crate::types::Outcome::Ok(())
                 }
                 Err(payload) => {
                     let message = payload_to_string(&payload);
                     std::mem::forget(payload);
                     let panic_payload = crate::types::outcome::PanicPayload::new(message);
-                    let _ = result_tx.send(
-                        &cx,
-                        Err::<T, JoinError>(JoinError::Panicked(panic_payload.clone())),
-                    );
+                    let result = Err(JoinError::Panicked(panic_payload.clone()));
+                    let _ = result_tx.send(&cx, completion.finish(result));
                     crate::types::Outcome::Panicked(panic_payload)
                 }
             }
@@ -3241,20 +3595,20 @@ impl RuntimeState {
         let system_cx = self.create_system_cx();
         let (task_id, handle, cx, result_tx, spawn_effects) =
             self.create_task_infrastructure(&system_cx, region, budget, false)?;
+        let completion = handle.completion_guard();
         let wrapped_future = async move {
+            completion.start();
             match (CatchUnwind { inner: future }).await {
                 Ok(result) => {
-                    let _ = result_tx.send(&cx, Ok::<_, JoinError>(result));
+                    let _ = result_tx.send(&cx, completion.finish(Ok(result)));
                     crate::types::Outcome::Ok(())
                 }
                 Err(payload) => {
                     let message = payload_to_string(&payload);
                     std::mem::forget(payload);
                     let panic_payload = crate::types::outcome::PanicPayload::new(message);
-                    let _ = result_tx.send(
-                        &cx,
-                        Err::<T, JoinError>(JoinError::Panicked(panic_payload.clone())),
-                    );
+                    let result = Err(JoinError::Panicked(panic_payload.clone()));
+                    let _ = result_tx.send(&cx, completion.finish(result));
                     crate::types::Outcome::Panicked(panic_payload)
                 }
             }
@@ -3330,6 +3684,7 @@ impl RuntimeState {
         if !region_record.state().can_accept_work() {
             return Err(SpawnError::RegionClosed(region));
         }
+        self.check_spawn_capacity(region)?;
 
         let now = self.current_runtime_time();
         let idx = self.insert_pooled_task_with(|idx, record| {
@@ -3382,6 +3737,8 @@ impl RuntimeState {
             };
             return Err(error);
         }
+        self.observe_spawn_capacity();
+        self.note_region_task_spawned(region, task_id, now);
 
         // Capability context, linked exactly as create_task_infrastructure
         // does, so cancellation and observability behave identically.
@@ -3405,11 +3762,18 @@ impl RuntimeState {
         )
         .with_blocking_pool_handle(self.blocking_pool_handle())
         .with_logical_clock(logical_clock)
+        .with_deadline_clamp_slack(self.deadline_clamp_slack)
+        .with_capability_audit(self.capability_audit.clone())
         .with_spawn_gateway(self.spawn_gateway.clone())
         .with_pending_spawn_counter(
             self.regions
                 .get(region.arena_index())
                 .map(crate::record::RegionRecord::pending_spawn_handle),
+        )
+        .with_disposal_slot(
+            self.regions
+                .get(region.arena_index())
+                .map(crate::record::RegionRecord::disposal_slot),
         );
         // Mailbox admission is visible in RuntimeState before its caller can
         // publish the first scheduler lane. Cancellation mutates this Cx while
@@ -3610,25 +3974,25 @@ impl RuntimeState {
 
         let (task_id, handle, cx, result_tx, spawn_effects) =
             self.create_task_infrastructure(caller_cx, region, budget, false)?;
+        let completion = handle.completion_guard();
 
         // Wrap the future to send the result through the channel. Panics must
         // surface as `JoinError::Panicked` rather than silently closing the
         // channel and looking like cancellation to the join handle.
         let wrapped_future = async move {
+            completion.start();
             spawn_effects.dispatch();
             match (CatchUnwind { inner: future }).await {
                 Ok(result) => {
-                    let _ = result_tx.send(&cx, Ok::<_, JoinError>(result));
+                    let _ = result_tx.send(&cx, completion.finish(Ok(result)));
                     crate::types::Outcome::Ok(())
                 }
                 Err(payload) => {
                     let message = payload_to_string(&payload);
                     std::mem::forget(payload);
                     let panic_payload = crate::types::outcome::PanicPayload::new(message);
-                    let _ = result_tx.send(
-                        &cx,
-                        Err::<T, JoinError>(JoinError::Panicked(panic_payload.clone())),
-                    );
+                    let result = Err(JoinError::Panicked(panic_payload.clone()));
+                    let _ = result_tx.send(&cx, completion.finish(result));
                     crate::types::Outcome::Panicked(panic_payload)
                 }
             }
@@ -4390,6 +4754,42 @@ impl RuntimeState {
         self.tasks.live_task_count()
     }
 
+    /// Checks the runtime live-task limit, then the `max_live_tasks` of
+    /// `region` and every ancestor, each bounding its whole subtree.
+    ///
+    /// Subtree counts are maintained incrementally by the region records, so
+    /// this only walks the ancestor chain.
+    pub(crate) fn check_spawn_capacity(&self, region: RegionId) -> Result<(), SpawnError> {
+        if self.spawn_capacity.limit().is_some() {
+            self.spawn_capacity.check(self.live_task_count())?;
+        }
+        let mut next = Some(region);
+        while let Some(id) = next {
+            let Some(record) = self.regions.get(id.arena_index()) else {
+                break;
+            };
+            if let Some(limit) = record.max_live_tasks() {
+                let current = record.subtree_task_count();
+                if current >= limit {
+                    self.spawn_capacity.record_rejection();
+                    return Err(SpawnError::AtCapacity {
+                        scope: CapacityScope::Region(id),
+                        limit,
+                        current,
+                    });
+                }
+            }
+            next = record.parent;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn observe_spawn_capacity(&self) {
+        if !self.spawn_capacity.is_unlimited() {
+            self.spawn_capacity.observe_live(self.live_task_count());
+        }
+    }
+
     /// Counts live regions.
     #[must_use]
     pub fn live_region_count(&self) -> usize {
@@ -4465,6 +4865,142 @@ impl RuntimeState {
             .note_transition(old_state, new_state);
     }
 
+    /// Arms the shutdown recorder so subsequent cancel, completion, and
+    /// region-close transitions feed a [`ShutdownReport`].
+    pub(crate) fn begin_shutdown_report(&mut self, started_at: Time, budget: Duration) {
+        self.shutdown_recorder = Some(Box::new(ShutdownRecorder::new(started_at, budget)));
+    }
+
+    /// Marks the end of the shutdown cancel fan-out.
+    pub(crate) fn note_shutdown_fanout_dispatched(&mut self, now: Time) {
+        if let Some(recorder) = self.shutdown_recorder.as_deref_mut() {
+            recorder.fanout_dispatched(now);
+        }
+    }
+
+    /// Disarms the shutdown recorder and builds its report as of the current
+    /// runtime time.
+    pub(crate) fn take_shutdown_report(
+        &mut self,
+        incomplete_reason: Option<String>,
+    ) -> Option<ShutdownReport> {
+        let recorder = self.shutdown_recorder.take()?;
+        Some(recorder.finish(self.current_runtime_time(), incomplete_reason))
+    }
+
+    fn note_shutdown_region_transition(&mut self, region_id: RegionId, new_state: RegionState) {
+        if self.shutdown_recorder.is_none() {
+            return;
+        }
+        let now = self.current_runtime_time();
+        if let Some(recorder) = self.shutdown_recorder.as_deref_mut() {
+            match new_state {
+                RegionState::Finalizing => recorder.region_finalizing(region_id, now),
+                RegionState::Closed => recorder.region_closed(region_id, now),
+                _ => {}
+            }
+        }
+    }
+
+    fn shutdown_task_label(&self, task: &TaskRecord) -> Option<String> {
+        self.shutdown_recorder.as_ref()?;
+        task.cx_inner.as_ref()?.read().task_type.clone()
+    }
+
+    /// Returns the hub that publishes this runtime's region lifecycle events.
+    #[must_use]
+    pub fn region_events(&self) -> &Arc<RegionEventHub> {
+        &self.region_events
+    }
+
+    /// Subscribes to region lifecycle events accepted by `filter`.
+    #[must_use]
+    pub fn subscribe_region_events(&self, filter: RegionEventFilter) -> RegionEventStream {
+        self.region_events.subscribe(filter)
+    }
+
+    /// Sets the class label reported in a region's lifecycle events.
+    ///
+    /// Events already published keep the label they were published with.
+    /// Returns `false` if the region does not exist.
+    pub fn set_region_label(&mut self, region: RegionId, label: impl Into<Arc<str>>) -> bool {
+        let Some(record) = self.regions.get(region.arena_index()) else {
+            return false;
+        };
+        record.set_label(Some(label.into()));
+        true
+    }
+
+    fn publish_region_event(&self, region: &RegionRecord, at: Time, detail: RegionEventDetail) {
+        let label = region.label();
+        self.region_events.publish(at, region.id, label.as_deref(), detail);
+    }
+
+    fn note_region_created(&self, region_id: RegionId, parent: Option<RegionId>, now: Time) {
+        if !self.region_events.has_subscribers() {
+            return;
+        }
+        if let Some(region) = self.regions.get(region_id.arena_index()) {
+            self.publish_region_event(region, now, RegionEventDetail::Created { parent });
+        }
+    }
+
+    fn note_region_task_spawned(&self, region_id: RegionId, task: TaskId, now: Time) {
+        if !self.region_events.has_subscribers() {
+            return;
+        }
+        if let Some(region) = self.regions.get(region_id.arena_index())
+            && region.task_counts().spawned == 1
+        {
+            self.publish_region_event(region, now, RegionEventDetail::FirstTaskSpawned { task });
+        }
+    }
+
+    fn note_region_cancel_requested(&self, region_id: RegionId, reason: &CancelReason, now: Time) {
+        if !self.region_events.has_subscribers() {
+            return;
+        }
+        if let Some(region) = self.regions.get(region_id.arena_index()) {
+            let detail = RegionEventDetail::CancelRequested {
+                cancel_kind: reason.kind,
+                origin_region: reason.origin_region,
+                origin_task: reason.origin_task,
+            };
+            self.publish_region_event(region, now, detail);
+        }
+    }
+
+    fn note_region_drain_started(&self, region_id: RegionId, now: Time) {
+        if !self.region_events.has_subscribers() {
+            return;
+        }
+        if let Some(region) = self.regions.get(region_id.arena_index())
+            && region.mark_drain_announced()
+        {
+            self.publish_region_event(region, now, RegionEventDetail::DrainStarted);
+        }
+    }
+
+    /// Publishes the closed event; must run before the record is removed.
+    fn note_region_closed(&self, region_id: RegionId, now: Time) {
+        if !self.region_events.has_subscribers() {
+            return;
+        }
+        if let Some(region) = self.regions.get(region_id.arena_index()) {
+            let status = region
+                .close_outcome()
+                .map_or(RegionCloseStatus::Ok, |outcome| {
+                    RegionCloseStatus::from_severity(outcome.severity())
+                });
+            let detail = RegionEventDetail::Closed {
+                status,
+                lifetime_nanos: now.duration_since(region.created_at()),
+                tasks: region.task_counts(),
+            };
+            self.publish_region_event(region, now, detail);
+        }
+    }
+
     /// Returns true if the runtime is quiescent (no live work).
     ///
     /// A runtime is quiescent when:
@@ -4678,6 +5214,10 @@ impl RuntimeState {
 
             // Store this region's reason for child chain building
             region_reasons.insert(rid, region_reason.clone());
+            if let Some(recorder) = self.shutdown_recorder.as_deref_mut() {
+                recorder.region_cancel_requested(rid, node.parent, now);
+            }
+            self.note_region_cancel_requested(rid, &region_reason, now);
             let region_cancel_kind = region_reason.kind;
 
             self.record_trace_event(|seq| {
@@ -4710,6 +5250,7 @@ impl RuntimeState {
                             },
                         )
                     });
+                    self.note_region_drain_started(rid, now);
                 } else if region.state() != crate::record::region::RegionState::Closed {
                     region.strengthen_cancel_reason(region_reason);
                 }
@@ -4764,6 +5305,9 @@ impl RuntimeState {
                     self.record_task_trace_event(task_id, |seq| {
                         TraceEvent::cancel_request(seq, now, task_id, rid, task_reason.clone())
                     });
+                    if let Some(recorder) = self.shutdown_recorder.as_deref_mut() {
+                        recorder.task_cancel_requested(task_id, rid, now);
+                    }
                 }
 
                 if changed && publication.is_published() {
@@ -4888,7 +5432,7 @@ impl RuntimeState {
         };
 
         let waiter_count = waiters.len();
-        let (owner, completion, close_outcome, observer, retired_cancel_wakers) = {
+        let (owner, completion, close_outcome, observer, retired_cancel_wakers, shutdown_label) = {
             let Some(task) = self.task(task_id) else {
                 // Defensive: if the task vanished between the
                 // update_task above and here, return the waiters we
@@ -4940,12 +5484,14 @@ impl RuntimeState {
             };
             let owner = task.owner;
             let completion = TaskCompletionKind::from_state(&task.state);
+            let shutdown_label = self.shutdown_task_label(task);
             (
                 owner,
                 completion,
                 close_outcome,
                 observer,
                 retired_cancel_wakers,
+                shutdown_label,
             )
         };
         // br-asupersync-ndhjfj: `waiters` was already taken atomically
@@ -4959,6 +5505,7 @@ impl RuntimeState {
             waiters,
             observer,
             retired_cancel_wakers,
+            shutdown_label,
             true,
         )
     }
@@ -5017,6 +5564,7 @@ impl RuntimeState {
         };
         let owner = task.owner;
         let completion = TaskCompletionKind::from_state(&task.state);
+        let shutdown_label = self.shutdown_task_label(task);
         self.finish_task_completion(
             task_id,
             owner,
@@ -5025,6 +5573,7 @@ impl RuntimeState {
             waiters,
             observer,
             retired_cancel_wakers,
+            shutdown_label,
             false,
         )
     }
@@ -5039,6 +5588,7 @@ impl RuntimeState {
         waiters: SmallVec<[TaskId; 4]>,
         observer: TaskCompletionObserver,
         retired_cancel_wakers: TaskCompletionRetirements,
+        shutdown_label: Option<String>,
         remove_embedded_record: bool,
     ) -> TaskCompletionEffects {
         if !matches!(completion, TaskCompletionKind::Cancelled) {
@@ -5099,8 +5649,27 @@ impl RuntimeState {
         // orphaned obligations from blocking region close (deadlock).
         // Uses the holder secondary index for O(obligations_per_task) instead of O(arena_capacity).
         let orphaned = self.obligations.sorted_pending_ids_for_holder(task_id);
+        let mut force_aborted = 0u64;
         for ob_id in orphaned {
-            let _ = self.abort_obligation(ob_id, ObligationAbortReason::Cancel);
+            if self
+                .abort_obligation(ob_id, ObligationAbortReason::Cancel)
+                .is_ok()
+            {
+                force_aborted += 1;
+            }
+        }
+        if self.shutdown_recorder.is_some() {
+            let now = self.current_runtime_time();
+            if let Some(recorder) = self.shutdown_recorder.as_deref_mut() {
+                recorder.obligations_aborted(owner, force_aborted);
+                recorder.task_completed(
+                    task_id,
+                    owner,
+                    completion.shutdown_outcome(),
+                    shutdown_label,
+                    now,
+                );
+            }
         }
 
         // Embedded tables still own their record here. External-shard callers
@@ -5113,19 +5682,30 @@ impl RuntimeState {
             self.notify_runtime_epoch_advance(super::epoch_tracker::ModuleId::TaskTable);
         }
 
+        // Resolve the task's links against its final outcome before the
+        // region can advance: cancelled partners keep it open.
+        let exit_deliveries = close_outcome.as_ref().map(|outcome| {
+            let reason = crate::monitor::DownReason::from_task_outcome(outcome);
+            self.resolve_link_exits(task_id, owner, reason)
+        });
+
         // Remove task from owning region to prevent memory leak
         if let Some(region) = self.regions.get(owner.arena_index()) {
-            if let Some(outcome) = close_outcome {
-                region.record_close_outcome(outcome);
-            }
-            region.remove_task(task_id);
+            region.retire_task(task_id, close_outcome, completion.severity());
         }
 
         // Advance region state if possible (e.g. if this was the last task)
         self.advance_region_state(owner);
 
         let mut observer = observer;
+        if let Some(deliveries) = exit_deliveries {
+            observer.attach_exit_deliveries(deliveries);
+        }
         observer.attach_epoch_telemetry(self.take_epoch_telemetry());
+        if !self.spawn_capacity.is_unlimited() {
+            self.spawn_capacity.observe_live(self.live_task_count());
+            observer.attach_capacity_release(self.spawn_capacity.release_token());
+        }
         TaskCompletionEffects {
             waiters,
             observer,
@@ -5270,7 +5850,9 @@ impl RuntimeState {
                 self.timer_driver_handle(),
                 Some(entropy),
             )
-            .with_logical_clock(logical_clock);
+            .with_logical_clock(logical_clock)
+            .with_deadline_clamp_slack(self.deadline_clamp_slack)
+            .with_capability_audit(self.capability_audit.clone());
             cx.set_trace_buffer(self.trace_handle());
             cx.set_loser_drain_history_handle(self.loser_drain_history_handle());
             cx
@@ -5322,9 +5904,9 @@ impl RuntimeState {
         let mut masked = MaskedFinalizer::new(future, Arc::clone(&cleanup_cx.inner));
         let waker = std::task::Waker::noop();
         let mut poll_cx = std::task::Context::from_waker(waker);
-        let poll_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let poll_result = crate::runtime::panic_strategy::catch_unwind(|| {
             std::pin::Pin::new(&mut masked).poll(&mut poll_cx)
-        }));
+        });
         let polled_outcome: Outcome<(), Error> = match poll_result {
             Ok(Poll::Ready(())) => Outcome::Ok(()),
             Ok(Poll::Pending) => Outcome::Cancelled(CancelReason::shutdown()),
@@ -5561,7 +6143,17 @@ impl RuntimeState {
         if !accepts_finalizers {
             return false;
         }
+        self.push_async_finalizer(region_id, future)
+    }
 
+    /// Registers an async finalizer without the open-region gate.
+    ///
+    /// Used for runtime-owned finalizers installed at the finalization
+    /// boundary, after the region stopped accepting user registrations.
+    fn push_async_finalizer<F>(&mut self, region_id: RegionId, future: F) -> bool
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         let finalizer_id = self.allocate_finalizer_id();
         {
             let Some(region) = self.regions.get(region_id.arena_index()) else {
@@ -6093,11 +6685,28 @@ impl RuntimeState {
             match state {
                 crate::record::region::RegionState::Closing
                 | crate::record::region::RegionState::Draining => {
+                    if self.region_events.has_subscribers() {
+                        let now = self.current_runtime_time();
+                        self.note_region_drain_started(region_id, now);
+                    }
+
                     // Only a region with terminal tasks and closed children may enter
                     // finalization. Non-quiescent Closing/Draining regions stay put while
                     // task cleanup, child close propagation, or finalizer scheduling makes
                     // progress.
                     let transition_to_finalizing = if self.can_region_finalize(region_id) {
+                        // Disposers dropped without `dispose()` deferred their
+                        // cleanup to the region's queue. Drain it as a
+                        // runtime-owned finalizer, registered before the
+                        // finalize transition is projected below.
+                        let disposal = self
+                            .regions
+                            .get(region_id.arena_index())
+                            .and_then(crate::record::RegionRecord::close_disposal_slot);
+                        if let Some(queue) = disposal {
+                            self.push_async_finalizer(region_id, queue.finalize());
+                        }
+
                         let Some(region) = self.regions.get(region_id.arena_index()) else {
                             break;
                         };
@@ -6151,6 +6760,7 @@ impl RuntimeState {
                         };
                         if let Some((old_state, new_state)) = transition {
                             self.note_read_biased_region_snapshot_transition(old_state, new_state);
+                            self.note_shutdown_region_transition(region_id, new_state);
                             true
                         } else {
                             false
@@ -6280,6 +6890,7 @@ impl RuntimeState {
 
                         if closed.0 {
                             self.note_read_biased_region_snapshot_transition(closed.1, closed.2);
+                            self.note_shutdown_region_transition(region_id, closed.2);
                             if let Some(pos) =
                                 self.finalizing_regions.iter().position(|&r| r == region_id)
                             {
@@ -6312,6 +6923,7 @@ impl RuntimeState {
                                     Duration::from_nanos(now.duration_since(region.created_at()));
                                 self.metrics.region_closed(region_id, lifetime);
                             }
+                            self.note_region_closed(region_id, now);
                             self.resource_monitor.clear_region_priority(region_id);
 
                             if let Some(parent_id) = parent {
@@ -6333,6 +6945,9 @@ impl RuntimeState {
                                 self.root_region = None;
                             }
                             self.remember_closed_region(region_id, close_outcome);
+                            // EXIT-CLEANUP: links held by the region's tasks
+                            // end with it.
+                            self.links.cleanup_region(region_id);
                             // Cleanup: Remove the closed region from the arena to prevent memory leaks
                             self.regions.remove(region_id.arena_index());
                             // Drop the region's cancel-protocol state machine too;
@@ -6390,6 +7005,146 @@ impl RuntimeState {
         Arc::clone(&self.loser_drain_history)
     }
 
+    /// Returns the registry of live supervisors.
+    ///
+    /// See [`export_tree`](crate::supervision::export_tree) for DOT/JSON output.
+    #[must_use]
+    pub fn supervision_tree(&self) -> &crate::supervision::SupervisionTree {
+        &self.supervision_tree
+    }
+
+    /// Returns the links between tasks.
+    #[must_use]
+    pub fn links(&self) -> &crate::link::LinkSet {
+        &self.links
+    }
+
+    /// Links two tasks so that each learns of the other's termination.
+    ///
+    /// When either task completes, the link is resolved against the partner's
+    /// [`Cx::trap_exits`](crate::Cx::trap_exits) flag as it stands then. A
+    /// trapping partner receives an [`ExitSignal`](crate::link::ExitSignal)
+    /// in the mailbox designated with [`set_exit_mailbox`](Self::set_exit_mailbox);
+    /// any other partner is cancelled if the exit was abnormal.
+    ///
+    /// Linking to a task that has already completed fires at once
+    /// (**EXIT-DEAD**): cancellations are queued for the scheduler, and the
+    /// returned deliveries must be dispatched after the runtime-state lock is
+    /// released.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if either id names neither a live task nor a task
+    /// whose exit the link set still remembers.
+    pub fn link_tasks(
+        &mut self,
+        task_a: TaskId,
+        task_b: TaskId,
+    ) -> Result<(crate::link::LinkRef, crate::link::ExitDeliveries), StaleId> {
+        let region_a = self.link_region(task_a)?;
+        let region_b = self.link_region(task_b)?;
+        let link_ref = self.links.establish(task_a, region_a, task_b, region_b);
+        let tasks = &self.tasks;
+        let pending = self
+            .links
+            .take_pending_exits(|peer| task_traps_exits(tasks, peer));
+        Ok((link_ref, self.apply_link_exits(pending)))
+    }
+
+    /// Removes a link established by [`link_tasks`](Self::link_tasks).
+    ///
+    /// Returns `true` if the link was still live.
+    pub fn unlink_tasks(&mut self, link_ref: crate::link::LinkRef) -> bool {
+        self.links.unlink(link_ref)
+    }
+
+    /// Designates the mailbox that receives `task_id`'s trapped exit signals.
+    ///
+    /// Returns the previously designated mailbox.
+    pub fn set_exit_mailbox(
+        &mut self,
+        task_id: TaskId,
+        mailbox: crate::link::ExitMailbox,
+    ) -> Option<crate::link::ExitMailbox> {
+        self.links.set_exit_mailbox(task_id, mailbox)
+    }
+
+    /// Kills a task: requests its cancellation and marks its exit as
+    /// untrappable, so every linked partner is cancelled when it completes
+    /// (**EXIT-KILL**).
+    pub fn kill_task(
+        &mut self,
+        task_id: TaskId,
+        reason: &CancelReason,
+    ) -> CancellationEffects<bool> {
+        if self.task(task_id).is_some() {
+            self.links.mark_killed(task_id);
+        }
+        self.cancel_task(task_id, reason)
+    }
+
+    fn link_region(&self, task_id: TaskId) -> Result<RegionId, StaleId> {
+        match self.checked_task(task_id) {
+            Ok(record) => Ok(record.owner),
+            Err(stale) => self.links.exited_region(task_id).ok_or(stale),
+        }
+    }
+
+    /// Retires `task_id` from the link set and applies its exit actions.
+    fn resolve_link_exits(
+        &mut self,
+        task_id: TaskId,
+        owner: RegionId,
+        reason: crate::monitor::DownReason,
+    ) -> crate::link::ExitDeliveries {
+        let now = self.current_runtime_time();
+        let tasks = &self.tasks;
+        let batch = self
+            .links
+            .task_exited(task_id, owner, now, reason, |peer| task_traps_exits(tasks, peer));
+        self.apply_link_exits(batch)
+    }
+
+    /// Cancels the partners a link batch propagates to and returns the
+    /// trapped exits for post-lock delivery.
+    ///
+    /// Cancellations go through [`defer_cancel_dispatch`](Self::defer_cancel_dispatch)
+    /// like any other cancellation raised beneath the state lock. A trapping
+    /// partner with no exit mailbox cannot take its signal as a message, so an
+    /// abnormal exit cancels it instead of being lost.
+    fn apply_link_exits(
+        &mut self,
+        batch: crate::link::LinkExitBatch,
+    ) -> crate::link::ExitDeliveries {
+        use crate::link::LinkExitAction;
+
+        let (deliveries, actions) = self.links.route(batch);
+        let mut tasks_to_cancel = Vec::new();
+        let mut wakes = CancelWakeEffects::empty();
+        let mut requested = false;
+        for action in actions {
+            let (to, reason) = match action {
+                LinkExitAction::CancelPeer { to, reason, .. } => (to, reason),
+                LinkExitAction::DeliverExit { to, signal }
+                    if !matches!(signal.reason, crate::monitor::DownReason::Normal) =>
+                {
+                    (to, crate::link::linked_exit_cancel_reason(&signal.reason))
+                }
+                LinkExitAction::DeliverExit { .. } | LinkExitAction::Ignored { .. } => continue,
+            };
+            requested = true;
+            let (publish, task_wakes) = self.cancel_task(to, &reason).into_parts();
+            wakes.merge(task_wakes);
+            if publish {
+                tasks_to_cancel.push((to, reason.cleanup_budget().priority));
+            }
+        }
+        if requested {
+            self.defer_cancel_dispatch(CancellationEffects::new(tasks_to_cancel, wakes));
+        }
+        deliveries
+    }
+
     #[cfg(test)]
     pub(crate) fn record_finalizer_close_for_test(&mut self, region: RegionId) {
         self.record_finalizer_close(region);
@@ -6568,6 +7323,15 @@ impl RuntimeState {
     }
 }
 
+/// Reads a task's [`Cx::trap_exits`](crate::Cx::trap_exits) flag. A task
+/// without a live record or `Cx` does not trap.
+fn task_traps_exits(tasks: &TaskTable, task_id: TaskId) -> bool {
+    tasks
+        .task(task_id)
+        .and_then(|record| record.cx_inner.as_ref())
+        .is_some_and(|inner| inner.read().trap_exits)
+}
+
 impl Default for RuntimeState {
     fn default() -> Self {
         Self::new()
@@ -8170,6 +8934,24 @@ mod tests {
                 },
                 "ASUP-E008",
             ),
+            (
+                SpawnError::CapabilityDenied(
+                    crate::cx::Cx::for_testing()
+                        .restrict_capabilities(crate::cx::CapabilitySet::none(), "plugin")
+                        .expect("narrowing succeeds")
+                        .check_capability(crate::cx::Capability::BlockingPool)
+                        .expect_err("withheld capability"),
+                ),
+                "ASUP-E009",
+            ),
+            (
+                SpawnError::AtCapacity {
+                    scope: CapacityScope::Runtime,
+                    limit: 4,
+                    current: 4,
+                },
+                "ASUP-E010",
+            ),
         ];
         let mut seen = std::collections::BTreeSet::new();
         for (error, expected_code) in cases {
@@ -8188,7 +8970,197 @@ mod tests {
                 "duplicate error code {expected_code}"
             );
         }
-        assert_eq!(seen.len(), 8, "every variant has a distinct code");
+        assert_eq!(seen.len(), 10, "every variant has a distinct code");
+    }
+
+    #[test]
+    fn runtime_live_task_limit_rejects_then_releases_waiter_on_completion() {
+        init_test("runtime_live_task_limit_rejects_then_releases_waiter_on_completion");
+
+        struct FlagWaker(AtomicBool);
+
+        impl std::task::Wake for FlagWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let mut state = RuntimeState::new();
+        state.set_spawn_capacity(SpawnCapacityConfig::default().with_max_live_tasks(2));
+        let root = state.create_root_region(Budget::INFINITE);
+        let (first, _first_handle) = state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect("first spawn fits");
+        let _second = state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect("second spawn fits");
+        let err = state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect_err("third spawn exceeds the limit");
+        assert_eq!(
+            err,
+            SpawnError::AtCapacity {
+                scope: CapacityScope::Runtime,
+                limit: 2,
+                current: 2,
+            }
+        );
+        assert_eq!(state.live_task_count(), 2, "rejection leaves no record");
+
+        let gate = Arc::clone(state.spawn_capacity());
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut task_cx = Context::from_waker(&waker);
+        let mut wait = gate.wait();
+        assert!(std::pin::Pin::new(&mut wait).poll(&mut task_cx).is_pending());
+
+        let _ = state.complete_task(first, Outcome::Ok(()));
+        let (_waiters, observer) = state.task_completed(first).into_parts();
+        assert!(
+            !flag.0.load(Ordering::SeqCst),
+            "capacity waiters are not woken under the state lock"
+        );
+        observer.dispatch();
+        assert!(flag.0.load(Ordering::SeqCst), "completion releases the waiter");
+        assert!(std::pin::Pin::new(&mut wait).poll(&mut task_cx).is_ready());
+        let _third = state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect("freed capacity admits the waiter");
+
+        let metrics = gate.metrics();
+        assert_eq!(metrics.live_tasks, 2);
+        assert_eq!(metrics.peak_live_tasks, 2);
+        assert_eq!(metrics.rejections, 1);
+        assert_eq!(metrics.waits, 1);
+        crate::test_complete!("runtime_live_task_limit_rejects_then_releases_waiter_on_completion");
+    }
+
+    #[test]
+    fn completion_dispatches_link_exits_by_cx_trap_flag() {
+        use crate::link::ExitMailbox;
+
+        init_test("completion_dispatches_link_exits_by_cx_trap_flag");
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        let mut spawn = || {
+            state
+                .create_task(root, Budget::INFINITE, async {})
+                .expect("spawn")
+                .0
+        };
+        let (plain, trapper, crashing, killed) = (spawn(), spawn(), spawn(), spawn());
+        let trapper_cx = state
+            .task(trapper)
+            .and_then(|record| record.cx.clone())
+            .expect("task cx");
+        trapper_cx.trap_exits(true);
+        let (tx, mut rx) = crate::channel::mpsc::channel::<crate::link::ExitSignal>(4);
+        let _ = state.set_exit_mailbox(trapper, ExitMailbox::from_sender(tx));
+        for (a, b) in [(plain, crashing), (trapper, crashing), (trapper, killed)] {
+            let (_, owed) = state.link_tasks(a, b).expect("link live tasks");
+            assert!(owed.is_empty());
+        }
+
+        assert!(state.complete_task(crashing, Outcome::Err(Error::new(ErrorKind::Internal))));
+        let (_waiters, observer) = state.task_completed(crashing).into_parts();
+        assert!(rx.try_recv().is_err(), "exits are not delivered under the state lock");
+        observer.dispatch();
+        let signal = rx.try_recv().expect("trapped exit delivered");
+        assert_eq!(signal.from, crashing);
+        assert!(state.task(trapper).and_then(TaskRecord::cancel_reason).is_none());
+        assert!(
+            state.task(plain).and_then(TaskRecord::cancel_reason).is_some(),
+            "a non-trapping partner is cancelled"
+        );
+        assert!(!state.take_deferred_cancel_dispatches().is_empty());
+
+        let reason = CancelReason::user("kill");
+        let _ = state.kill_task(killed, &reason);
+        assert!(state.complete_task(killed, Outcome::Cancelled(reason)));
+        let (_waiters, observer) = state.task_completed(killed).into_parts();
+        observer.dispatch();
+        assert!(rx.try_recv().is_err(), "a kill cannot be trapped");
+        assert!(state.task(trapper).and_then(TaskRecord::cancel_reason).is_some());
+        crate::test_complete!("completion_dispatches_link_exits_by_cx_trap_flag");
+    }
+
+    #[test]
+    fn subtree_live_task_limit_bounds_nested_regions() {
+        init_test("subtree_live_task_limit_bounds_nested_regions");
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        // Created through the region table, which links subtree counts.
+        let nest = |state: &mut RuntimeState, parent| {
+            state
+                .create_child_region(parent, Budget::INFINITE)
+                .expect("create child region")
+        };
+        let parent = nest(&mut state, root);
+        let child = nest(&mut state, parent);
+        let grandchild = nest(&mut state, child);
+        state.set_region_limits(
+            parent,
+            RegionLimits {
+                max_live_tasks: Some(3),
+                ..RegionLimits::UNLIMITED
+            },
+        );
+        // The child's own subtree limit is looser than its parent's headroom,
+        // and `max_tasks` still counts only the region's own tasks.
+        state.set_region_limits(
+            child,
+            RegionLimits {
+                max_tasks: Some(1),
+                max_live_tasks: Some(10),
+                ..RegionLimits::UNLIMITED
+            },
+        );
+
+        let mut spawned = Vec::new();
+        for region in [parent, child, grandchild] {
+            let (task, _handle) = state
+                .create_task(region, Budget::INFINITE, async {})
+                .expect("subtree below its limit");
+            spawned.push(task);
+        }
+        let subtree_count = |state: &RuntimeState, region: RegionId| {
+            state
+                .regions
+                .get(region.arena_index())
+                .expect("region")
+                .subtree_task_count()
+        };
+        assert_eq!(subtree_count(&state, parent), 3);
+        assert_eq!(subtree_count(&state, child), 2);
+        assert_eq!(subtree_count(&state, root), 3);
+
+        let expected = SpawnError::AtCapacity {
+            scope: CapacityScope::Region(parent),
+            limit: 3,
+            current: 3,
+        };
+        for region in [grandchild, child, parent] {
+            let err = state
+                .create_task(region, Budget::INFINITE, async {})
+                .expect_err("parent subtree is full");
+            assert_eq!(err, expected);
+        }
+        state
+            .create_task(root, Budget::INFINITE, async {})
+            .expect("regions outside the subtree are unaffected");
+        assert_eq!(state.spawn_capacity().metrics().rejections, 3);
+
+        // Completing a grandchild task frees headroom all the way up.
+        let done = spawned[2];
+        let _ = state.complete_task(done, Outcome::Ok(()));
+        let (_waiters, observer) = state.task_completed(done).into_parts();
+        observer.dispatch();
+        assert_eq!(subtree_count(&state, parent), 2);
+        assert_eq!(subtree_count(&state, root), 3);
+        state
+            .create_task(grandchild, Budget::INFINITE, async {})
+            .expect("freed subtree capacity admits the spawn");
+        crate::test_complete!("subtree_live_task_limit_bounds_nested_regions");
     }
 
     #[derive(Default)]
@@ -9906,6 +10878,83 @@ mod tests {
         id
     }
 
+    #[test]
+    fn stale_task_id_after_slot_reuse_is_rejected() {
+        init_test("stale_task_id_after_slot_reuse_is_rejected");
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        let old = insert_task(&mut state, root);
+        assert!(state.checked_task(old).is_ok());
+
+        state.remove_task(old).expect("remove old task");
+        let freed = state.checked_task(old).expect_err("freed id is stale");
+        assert_eq!(freed.current, None, "freed slot is vacant");
+
+        let new = insert_task(&mut state, root);
+        assert_eq!(new.arena_index().index(), old.arena_index().index());
+        let stale = state.checked_task(old).expect_err("reused slot");
+        assert_eq!(stale.kind, IdKind::Task);
+        assert_eq!(stale.generation, Some(old.arena_index().generation()));
+        assert_eq!(stale.current, Some(new.arena_index().generation()));
+        assert!(state.task(old).is_none(), "plain lookup must not alias");
+
+        // A strict reference to the old task never resolves to the new one;
+        // a lenient one opts into "whatever is there now".
+        let strict = IdRef::exact(IdKind::Task, old.arena_index());
+        assert_eq!(state.resolve_id_ref(strict), Err(stale));
+        let lenient = format!("T{}", old.arena_index().index());
+        let lenient = IdRef::parse(IdKind::Task, &lenient, crate::types::IdParseMode::Lenient)
+            .expect("lenient parse");
+        assert_eq!(state.resolve_id_ref(lenient), Ok(new.arena_index()));
+        crate::test_complete!("stale_task_id_after_slot_reuse_is_rejected");
+    }
+
+    #[test]
+    fn cancel_with_stale_task_id_is_rejected() {
+        init_test("cancel_with_stale_task_id_is_rejected");
+        let mut state = RuntimeState::new();
+        let root = state.create_root_region(Budget::INFINITE);
+        let old = insert_task(&mut state, root);
+        state.remove_task(old).expect("remove old task");
+        let new = insert_task(&mut state, root);
+
+        let reason = CancelReason::user("cancel from stale dashboard id");
+        let stale = state
+            .cancel_task_checked(old, &reason)
+            .err()
+            .expect("stale cancel must be rejected");
+        assert_eq!(stale.current, Some(new.arena_index().generation()));
+        assert!(
+            !state.task(new).expect("new task").is_cancelling(),
+            "stale cancel must not land on the slot's new occupant"
+        );
+
+        let (changed, wakes) = state
+            .cancel_task_checked(new, &reason)
+            .expect("live id")
+            .into_parts();
+        wakes.dispatch();
+        assert!(changed);
+        assert!(state.task(new).expect("new task").is_cancelling());
+        crate::test_complete!("cancel_with_stale_task_id_is_rejected");
+    }
+
+    #[test]
+    fn stale_region_and_obligation_ids_are_typed_errors() {
+        init_test("stale_region_and_obligation_ids_are_typed_errors");
+        let state = RuntimeState::new();
+        let region = RegionId::from_arena(ArenaIndex::new(3, 1));
+        let stale = state.checked_region(region).expect_err("never allocated");
+        assert_eq!((stale.kind, stale.current), (IdKind::Region, None));
+        let obligation = ObligationId::from_arena(ArenaIndex::new(0, 2));
+        let stale = state
+            .checked_obligation(obligation)
+            .expect_err("never allocated");
+        assert_eq!(stale.kind, IdKind::Obligation);
+        assert_eq!(stale.to_string(), "stale ObligationId O0:2 (slot is vacant)");
+        crate::test_complete!("stale_region_and_obligation_ids_are_typed_errors");
+    }
+
     #[test]
     fn cx_trace_emits_user_trace_event() {
         init_test("cx_trace_emits_user_trace_event");
@@ -15112,6 +16161,7 @@ mod tests {
             io_driver: None,
             timer_driver: None,
             logical_clock_mode: LogicalClockMode::Lamport,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
             cancel_attribution: CancelAttributionConfig::default(),
             entropy_source: Arc::new(OsEntropy),
             blocking_pool: None,
@@ -15354,6 +16404,7 @@ mod tests {
             io_driver: None,
             timer_driver: None,
             logical_clock_mode: LogicalClockMode::Lamport,
+            deadline_clamp_slack: crate::time::DEFAULT_DEADLINE_CLAMP_SLACK,
             cancel_attribution: CancelAttributionConfig::default(),
             entropy_source: Arc::new(OsEntropy),
             blocking_pool: None,
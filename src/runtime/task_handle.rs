@@ -5,6 +5,9 @@
 
 use crate::channel::oneshot;
 use crate::cx::Cx;
+use crate::runtime::task_lifecycle::{
+    CompletionHookError, TaskCompletionGuard, TaskLifecycle, TaskLifecycleState, TaskStarted,
+};
 use crate::types::{CancelReason, CxInner, PanicPayload, TaskId};
 use parking_lot::RwLock;
 use std::sync::{Arc, Weak};
@@ -39,6 +42,8 @@ impl std::error::Error for JoinError {}
 /// It provides:
 /// - The task ID for identification and debugging
 /// - A way to await the task's result via `join()`
+/// - Lifecycle hooks that need no join: `started()`, `state()`, and
+///   `on_completion()` callbacks
 ///
 /// # Ownership
 ///
@@ -77,6 +82,8 @@ pub struct TaskHandle<T> {
     requested_cancel_reason: Arc<RwLock<Option<CancelReason>>>,
     /// Whether this handle already consumed a terminal join result.
     terminal_consumed: bool,
+    /// Start/completion hooks shared with the task's spawn wrapper.
+    lifecycle: Arc<TaskLifecycle<T>>,
 }
 
 fn apply_or_defer_cancel_reason(
@@ -258,6 +265,7 @@ impl<T> TaskHandle<T> {
             admitted: None,
             requested_cancel_reason: Arc::new(RwLock::new(None)),
             terminal_consumed: false,
+            lifecycle: Arc::new(TaskLifecycle::new()),
         }
     }
 
@@ -281,6 +289,7 @@ impl<T> TaskHandle<T> {
             admitted: Some(admitted),
             requested_cancel_reason,
             terminal_consumed: false,
+            lifecycle: Arc::new(TaskLifecycle::new()),
         }
     }
 
@@ -325,6 +334,79 @@ impl<T> TaskHandle<T> {
         self.terminal_consumed || self.receiver.is_ready() || self.receiver.is_closed()
     }
 
+    /// Returns a future that resolves once the task has been polled.
+    ///
+    /// The future yields `true` after the task's first poll, or `false` if
+    /// the task reached a terminal state without ever running (cancelled
+    /// before admission, dropped with its region). It is independent of the
+    /// handle, so it can gate startup ordering while the handle is joined
+    /// elsewhere.
+    #[inline]
+    pub fn started(&self) -> TaskStarted<T> {
+        TaskStarted::new(Arc::clone(&self.lifecycle))
+    }
+
+    /// Returns a snapshot of the task's lifecycle state.
+    ///
+    /// `Draining` means the task is running with a cancellation request it
+    /// has not finished honoring. The snapshot is cheap (an atomic load plus,
+    /// for a running task, a read of its cancel flag) and may be stale by the
+    /// time the caller acts on it.
+    #[must_use]
+    pub fn state(&self) -> TaskLifecycleState {
+        if self.is_finished() {
+            return TaskLifecycleState::Terminal;
+        }
+        match self.lifecycle.state() {
+            TaskLifecycleState::Running
+                if self
+                    .live_inner()
+                    .is_some_and(|inner| inner.read().cancel_requested) =>
+            {
+                TaskLifecycleState::Draining
+            }
+            state => state,
+        }
+    }
+
+    /// Registers a callback that receives the task's outcome when it finishes.
+    ///
+    /// Callbacks run on the task's final poll, in registration order, before
+    /// the result is delivered to `join()`; they fire whether or not the
+    /// handle is ever joined or even kept. A task dropped before completing
+    /// reports `Err(JoinError::Cancelled(_))`. A panicking callback is
+    /// contained and does not affect the task or later callbacks; under
+    /// `panic = "abort"` it ends the process instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompletionHookError::Finished`] if the task already finished,
+    /// or [`CompletionHookError::LimitReached`] once
+    /// [`MAX_COMPLETION_CALLBACKS`](crate::runtime::task_lifecycle::MAX_COMPLETION_CALLBACKS)
+    /// callbacks are registered.
+    pub fn on_completion<F>(&self, callback: F) -> Result<(), CompletionHookError>
+    where
+        F: FnOnce(&Result<T, JoinError>) + Send + 'static,
+    {
+        self.lifecycle.register(Box::new(callback))
+    }
+
+    /// Number of completion callbacks that panicked so far.
+    #[inline]
+    #[must_use]
+    pub fn completion_callback_panics(&self) -> usize {
+        self.lifecycle.callback_panics()
+    }
+
+    /// Returns the task-side lifecycle guard for this handle's spawn wrapper.
+    pub(crate) fn completion_guard(&self) -> TaskCompletionGuard<T> {
+        TaskCompletionGuard::new(
+            Arc::clone(&self.lifecycle),
+            self.inner.clone(),
+            Arc::clone(&self.requested_cancel_reason),
+        )
+    }
+
     /// Waits for the task to complete and returns its result.
     ///
     /// This method yields until the spawned task completes, then returns its output value.
@@ -599,8 +681,10 @@ mod tests {
     )]
     use super::*;
     use crate::cx::cap;
+    use crate::lab::LabRuntime;
+    use crate::runtime::yield_now;
     use crate::test_utils::init_test_logging;
-    use crate::types::CancelKind;
+    use crate::types::{Budget, CancelKind, RegionId};
     use crate::util::ArenaIndex;
     use serde_json::{Value, json};
     use std::future::Future;
@@ -1514,4 +1598,245 @@ mod tests {
         );
         crate::test_complete!("task_handle_snapshot_scrubs_ids");
     }
+
+    fn lab_task<F>(runtime: &mut LabRuntime, region: RegionId, future: F) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (task_id, handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, future)
+            .expect("create task");
+        runtime.scheduler.lock().schedule(task_id, 0);
+        handle
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        std::pin::Pin::new(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn started_resolves_only_after_first_poll() {
+        init_test("started_resolves_only_after_first_poll");
+        let mut runtime = LabRuntime::with_seed(3);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let log = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        let task_log = Arc::clone(&log);
+        let handle = lab_task(&mut runtime, root, async move {
+            task_log.lock().push("task first poll");
+            yield_now().await;
+            task_log.lock().push("task done");
+        });
+        let mut started = handle.started();
+        let pending = poll_once(&mut started).is_pending();
+        crate::assert_with_log!(pending, "not started before scheduling", true, pending);
+
+        let observer_log = Arc::clone(&log);
+        let observer = handle.started();
+        let _observer = lab_task(&mut runtime, root, async move {
+            let ran = observer.await;
+            observer_log.lock().push(if ran { "observer" } else { "never ran" });
+        });
+        runtime.run_until_quiescent();
+
+        let log = log.lock().clone();
+        let first = log.iter().position(|e| *e == "task first poll");
+        let observed = log.iter().position(|e| *e == "observer");
+        crate::assert_with_log!(
+            first.is_some() && observed > first,
+            "observer wakes after the first poll",
+            "task first poll < observer",
+            log
+        );
+        let ready = poll_once(&mut started);
+        crate::assert_with_log!(
+            ready == Poll::Ready(true),
+            "started stays resolved",
+            Poll::Ready(true),
+            ready
+        );
+        crate::test_complete!("started_resolves_only_after_first_poll");
+    }
+
+    #[test]
+    fn state_walks_pending_running_draining_terminal_in_lab() {
+        init_test("state_walks_pending_running_draining_terminal_in_lab");
+        let mut runtime = LabRuntime::with_seed(5);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let handle = lab_task(&mut runtime, root, async {
+            let cx = Cx::current().expect("lab task has a current Cx");
+            while cx.checkpoint().is_ok() {
+                yield_now().await;
+            }
+            // Drain for a few polls after observing the cancellation.
+            for _ in 0..3 {
+                yield_now().await;
+            }
+        });
+
+        let mut seen = vec![handle.state()];
+        for step in 0..64 {
+            if step == 3 {
+                handle.abort();
+            }
+            runtime.step_for_test();
+            let state = handle.state();
+            if seen.last() != Some(&state) {
+                seen.push(state);
+            }
+            if state.is_terminal() {
+                break;
+            }
+        }
+
+        let expected = vec![
+            TaskLifecycleState::Pending,
+            TaskLifecycleState::Running,
+            TaskLifecycleState::Draining,
+            TaskLifecycleState::Terminal,
+        ];
+        crate::assert_with_log!(seen == expected, "lifecycle states", expected, seen);
+        crate::test_complete!("state_walks_pending_running_draining_terminal_in_lab");
+    }
+
+    #[test]
+    fn completion_callback_fires_for_unawaited_handle() {
+        init_test("completion_callback_fires_for_unawaited_handle");
+        let mut runtime = LabRuntime::with_seed(11);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let handle = lab_task(&mut runtime, root, async {
+            yield_now().await;
+            7_u32
+        });
+        for tag in ["first", "second"] {
+            let seen = Arc::clone(&seen);
+            handle
+                .on_completion(move |result| seen.lock().push((tag, result.clone())))
+                .expect("register callback");
+        }
+        drop(handle);
+        runtime.run_until_quiescent();
+
+        let seen = seen.lock().clone();
+        let expected = vec![("first", Ok(7)), ("second", Ok(7))];
+        crate::assert_with_log!(seen == expected, "callbacks in order", expected, seen);
+        crate::test_complete!("completion_callback_fires_for_unawaited_handle");
+    }
+
+    #[test]
+    fn completion_callback_panic_is_contained() {
+        init_test("completion_callback_panic_is_contained");
+        let mut runtime = LabRuntime::with_seed(13);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let seen = Arc::new(parking_lot::Mutex::new(None));
+        let mut handle = lab_task(&mut runtime, root, async { 21_u32 * 2 });
+        handle
+            .on_completion(|_| panic!("callback failure"))
+            .expect("register panicking callback");
+        let after = Arc::clone(&seen);
+        handle
+            .on_completion(move |result| *after.lock() = Some(result.clone()))
+            .expect("register recording callback");
+        runtime.run_until_quiescent();
+
+        let later = seen.lock().clone();
+        crate::assert_with_log!(
+            later == Some(Ok(42)),
+            "later callback still runs",
+            Some(Ok::<u32, JoinError>(42)),
+            later
+        );
+        let panics = handle.completion_callback_panics();
+        crate::assert_with_log!(panics == 1, "panic counted", 1, panics);
+        let joined = block_on(handle.join(&test_cx()));
+        crate::assert_with_log!(
+            joined == Ok(42),
+            "outcome unaffected",
+            Ok::<u32, JoinError>(42),
+            joined
+        );
+        let late = handle.on_completion(|_| {});
+        crate::assert_with_log!(
+            late == Err(CompletionHookError::Finished),
+            "registration after finish is refused",
+            Err::<(), _>(CompletionHookError::Finished),
+            late
+        );
+        crate::test_complete!("completion_callback_panic_is_contained");
+    }
+
+    #[test]
+    fn completion_callbacks_are_bounded() {
+        init_test("completion_callbacks_are_bounded");
+        let (_tx, rx) = oneshot::channel::<Result<(), JoinError>>();
+        let handle = TaskHandle::new(TaskId::new_for_test(30, 0), rx, Weak::new());
+        for _ in 0..crate::runtime::MAX_COMPLETION_CALLBACKS {
+            handle.on_completion(|_| {}).expect("under the limit");
+        }
+        let over = handle.on_completion(|_| {});
+        let expected = Err(CompletionHookError::LimitReached {
+            limit: crate::runtime::MAX_COMPLETION_CALLBACKS,
+        });
+        crate::assert_with_log!(over == expected, "limit enforced", expected, over);
+        crate::test_complete!("completion_callbacks_are_bounded");
+    }
+
+    #[test]
+    fn dropped_handle_releases_lifecycle_after_completion() {
+        init_test("dropped_handle_releases_lifecycle_after_completion");
+        let mut runtime = LabRuntime::with_seed(17);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let token = Arc::new(());
+        let handle = lab_task(&mut runtime, root, async {
+            yield_now().await;
+        });
+        let lifecycle = Arc::downgrade(&handle.lifecycle);
+        let held = Arc::clone(&token);
+        handle
+            .on_completion(move |_| drop(held))
+            .expect("register callback");
+        drop(handle);
+
+        runtime.run_until_quiescent();
+        let lifecycle_live = lifecycle.upgrade().is_some();
+        crate::assert_with_log!(!lifecycle_live, "lifecycle freed", false, lifecycle_live);
+        let captures = Arc::strong_count(&token);
+        crate::assert_with_log!(captures == 1, "callback captures freed", 1, captures);
+        crate::test_complete!("dropped_handle_releases_lifecycle_after_completion");
+    }
+
+    #[test]
+    fn task_dropped_before_running_reports_cancelled() {
+        init_test("task_dropped_before_running_reports_cancelled");
+        let (_tx, rx) = oneshot::channel::<Result<u8, JoinError>>();
+        let handle = TaskHandle::new(TaskId::new_for_test(31, 0), rx, Weak::new());
+        let seen = Arc::new(parking_lot::Mutex::new(None));
+        let slot = Arc::clone(&seen);
+        handle
+            .on_completion(move |result| *slot.lock() = Some(result.clone()))
+            .expect("register callback");
+        handle.abort_with_reason(CancelReason::shutdown());
+
+        // The spawn wrapper owns the guard; dropping an unpolled wrapper
+        // drops the guard without a result.
+        drop(handle.completion_guard());
+
+        let seen = seen.lock().clone();
+        let cancelled = matches!(
+            &seen,
+            Some(Err(JoinError::Cancelled(reason))) if reason.is_kind(CancelKind::Shutdown)
+        );
+        crate::assert_with_log!(cancelled, "callback sees cancellation", true, seen);
+        let started = poll_once(&mut handle.started());
+        crate::assert_with_log!(
+            started == Poll::Ready(false),
+            "never started",
+            Poll::Ready(false),
+            started
+        );
+        crate::test_complete!("task_dropped_before_running_reports_cancelled");
+    }
 }
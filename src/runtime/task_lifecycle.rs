@@ -0,0 +1,312 @@
+//! Lifecycle hooks shared between a spawned task and its [`TaskHandle`].
+//!
+//! Every spawn path wraps the user future before storing it. The wrapper
+//! holds a [`TaskCompletionGuard`] taken from the handle, marks the task as
+//! started on its first poll, and hands the typed join result to the guard
+//! just before delivering it on the join channel. That gives the handle three
+//! observations that do not require awaiting the result:
+//!
+//! - [`TaskHandle::started`] resolves once the task has been polled,
+//! - [`TaskHandle::state`] reports a cheap [`TaskLifecycleState`] snapshot,
+//! - [`TaskHandle::on_completion`] callbacks run with the outcome even when
+//!   nobody ever joins the handle.
+//!
+//! Callbacks run on the task's own poll, inside its finalize step, so they
+//! are ordered deterministically under the lab runtime and never outlive the
+//! task: if the stored future is dropped before it completes (cancelled
+//! before first poll, region teardown), the guard's `Drop` runs them with a
+//! cancellation outcome instead. A panicking callback is contained and
+//! counted; it cannot change the task's outcome or skip later callbacks.
+//! Containment goes through `panic_strategy::catch_unwind`, so under
+//! `panic = "abort"` a panicking callback ends the process like any other
+//! panic.
+//!
+//! [`TaskHandle`]: crate::runtime::TaskHandle
+//! [`TaskHandle::started`]: crate::runtime::TaskHandle::started
+//! [`TaskHandle::state`]: crate::runtime::TaskHandle::state
+//! [`TaskHandle::on_completion`]: crate::runtime::TaskHandle::on_completion
+
+use crate::runtime::panic_strategy;
+use crate::runtime::task_handle::JoinError;
+use crate::types::{CancelReason, CxInner};
+use parking_lot::{Mutex, RwLock};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+
+/// Maximum number of completion callbacks one task accepts.
+pub const MAX_COMPLETION_CALLBACKS: usize = 16;
+
+const STARTED: u8 = 0b01;
+const TERMINAL: u8 = 0b10;
+
+/// Snapshot of a spawned task's lifecycle as seen from its handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskLifecycleState {
+    /// Spawned but not yet polled.
+    Pending,
+    /// Polled at least once and not cancelled.
+    Running,
+    /// Running with a cancellation request it has not finished honoring.
+    Draining,
+    /// Completed, panicked, cancelled, or dropped; the outcome is final.
+    Terminal,
+}
+
+impl TaskLifecycleState {
+    /// Returns true for [`TaskLifecycleState::Terminal`].
+    #[inline]
+    #[must_use]
+    pub const fn is_terminal(self) -> bool {
+        matches!(self, Self::Terminal)
+    }
+}
+
+/// Error returned when a completion callback cannot be registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionHookError {
+    /// The task already finished; its outcome was delivered without the callback.
+    Finished,
+    /// The task already holds [`MAX_COMPLETION_CALLBACKS`] callbacks.
+    LimitReached {
+        /// The per-task callback limit.
+        limit: usize,
+    },
+}
+
+impl fmt::Display for CompletionHookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Finished => write!(f, "task already finished"),
+            Self::LimitReached { limit } => {
+                write!(f, "task already has {limit} completion callbacks")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompletionHookError {}
+
+type CompletionCallback<T> = Box<dyn FnOnce(&Result<T, JoinError>) + Send>;
+
+struct Hooks<T> {
+    started: Vec<Waker>,
+    callbacks: Vec<CompletionCallback<T>>,
+}
+
+/// State shared by a [`TaskHandle`](crate::runtime::TaskHandle) and the
+/// wrapper future of the task it observes.
+pub(crate) struct TaskLifecycle<T> {
+    phase: AtomicU8,
+    hooks: Mutex<Hooks<T>>,
+    callback_panics: AtomicUsize,
+}
+
+impl<T> TaskLifecycle<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            phase: AtomicU8::new(0),
+            hooks: Mutex::new(Hooks {
+                started: Vec::new(),
+                callbacks: Vec::new(),
+            }),
+            callback_panics: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pending, Running, or Terminal; draining is derived by the handle.
+    pub(crate) fn state(&self) -> TaskLifecycleState {
+        let phase = self.phase.load(Ordering::Acquire);
+        if phase & TERMINAL != 0 {
+            TaskLifecycleState::Terminal
+        } else if phase & STARTED != 0 {
+            TaskLifecycleState::Running
+        } else {
+            TaskLifecycleState::Pending
+        }
+    }
+
+    pub(crate) fn callback_panics(&self) -> usize {
+        self.callback_panics.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn register(
+        &self,
+        callback: CompletionCallback<T>,
+    ) -> Result<(), CompletionHookError> {
+        let mut hooks = self.hooks.lock();
+        if self.phase.load(Ordering::Acquire) & TERMINAL != 0 {
+            return Err(CompletionHookError::Finished);
+        }
+        if hooks.callbacks.len() >= MAX_COMPLETION_CALLBACKS {
+            return Err(CompletionHookError::LimitReached {
+                limit: MAX_COMPLETION_CALLBACKS,
+            });
+        }
+        hooks.callbacks.push(callback);
+        drop(hooks);
+        Ok(())
+    }
+
+    fn mark_started(&self) {
+        if self.phase.fetch_or(STARTED, Ordering::AcqRel) != 0 {
+            return;
+        }
+        let waiters = std::mem::take(&mut self.hooks.lock().started);
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+
+    /// Publishes the terminal phase and runs the registered callbacks, each
+    /// behind its own unwind boundary, outside the hooks lock.
+    fn finish(&self, result: &Result<T, JoinError>) {
+        let (waiters, callbacks) = {
+            let mut hooks = self.hooks.lock();
+            if self.phase.fetch_or(TERMINAL, Ordering::AcqRel) & TERMINAL != 0 {
+                return;
+            }
+            (
+                std::mem::take(&mut hooks.started),
+                std::mem::take(&mut hooks.callbacks),
+            )
+        };
+        for waker in waiters {
+            waker.wake();
+        }
+        for callback in callbacks {
+            if panic_strategy::catch_unwind(|| callback(result)).is_err() {
+                self.callback_panics.fetch_add(1, Ordering::AcqRel);
+                crate::tracing_compat::warn!("task completion callback panicked");
+            }
+        }
+    }
+
+    fn started_outcome(&self) -> Option<bool> {
+        let phase = self.phase.load(Ordering::Acquire);
+        if phase & STARTED != 0 {
+            Some(true)
+        } else if phase & TERMINAL != 0 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn poll_started(&self, cx: &Context<'_>) -> Poll<bool> {
+        if let Some(started) = self.started_outcome() {
+            return Poll::Ready(started);
+        }
+        let mut hooks = self.hooks.lock();
+        // Re-check under the lock: `mark_started` and `finish` take the
+        // waiters after publishing the phase, so a waker pushed here is
+        // never missed.
+        if let Some(started) = self.started_outcome() {
+            return Poll::Ready(started);
+        }
+        if !hooks.started.iter().any(|w| w.will_wake(cx.waker())) {
+            hooks.started.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> fmt::Debug for TaskLifecycle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskLifecycle")
+            .field("state", &self.state())
+            .field("callback_panics", &self.callback_panics())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Future returned by [`TaskHandle::started`](crate::runtime::TaskHandle::started).
+///
+/// Resolves to `true` once the task has been polled for the first time, or
+/// to `false` if it reached a terminal state without ever running.
+#[must_use = "futures do nothing unless polled"]
+pub struct TaskStarted<T> {
+    lifecycle: Arc<TaskLifecycle<T>>,
+}
+
+impl<T> TaskStarted<T> {
+    pub(crate) fn new(lifecycle: Arc<TaskLifecycle<T>>) -> Self {
+        Self { lifecycle }
+    }
+}
+
+impl<T> Future for TaskStarted<T> {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<bool> {
+        self.lifecycle.poll_started(cx)
+    }
+}
+
+impl<T> fmt::Debug for TaskStarted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskStarted")
+            .field("state", &self.lifecycle.state())
+            .finish()
+    }
+}
+
+/// The task-side half of a [`TaskLifecycle`], owned by the spawn wrapper.
+///
+/// Dropping the guard without [`finish`](Self::finish) means the task's
+/// future was dropped before it produced a result; the callbacks then see
+/// a cancellation with the strongest reason known for the task.
+pub(crate) struct TaskCompletionGuard<T> {
+    lifecycle: Arc<TaskLifecycle<T>>,
+    cx_inner: Weak<RwLock<CxInner>>,
+    requested_cancel_reason: Arc<RwLock<Option<CancelReason>>>,
+    finished: bool,
+}
+
+impl<T> TaskCompletionGuard<T> {
+    pub(crate) fn new(
+        lifecycle: Arc<TaskLifecycle<T>>,
+        cx_inner: Weak<RwLock<CxInner>>,
+        requested_cancel_reason: Arc<RwLock<Option<CancelReason>>>,
+    ) -> Self {
+        Self {
+            lifecycle,
+            cx_inner,
+            requested_cancel_reason,
+            finished: false,
+        }
+    }
+
+    /// Marks the first poll; later calls are no-ops.
+    pub(crate) fn start(&self) {
+        self.lifecycle.mark_started();
+    }
+
+    /// Runs the completion callbacks and returns `result` for delivery on
+    /// the join channel.
+    pub(crate) fn finish(mut self, result: Result<T, JoinError>) -> Result<T, JoinError> {
+        self.finished = true;
+        self.lifecycle.finish(&result);
+        result
+    }
+
+    fn dropped_reason(&self) -> CancelReason {
+        self.cx_inner
+            .upgrade()
+            .and_then(|inner| inner.read().cancel_reason.clone())
+            .or_else(|| self.requested_cancel_reason.read().clone())
+            .unwrap_or_else(|| CancelReason::user("task dropped before completion"))
+    }
+}
+
+impl<T> Drop for TaskCompletionGuard<T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let reason = self.dropped_reason();
+            self.lifecycle.finish(&Err(JoinError::Cancelled(reason)));
+        }
+    }
+}
@@ -4,9 +4,10 @@
 //! Part of the sharding refactor (bd-2ijqf) to reduce RuntimeState contention.
 
 use crate::record::task::{TaskPhase, TaskRecord};
+use crate::runtime::object_pool::{ObjectPoolConfig, ObjectPoolStats, ShardedPool};
 use crate::runtime::stored_task::StoredTask;
 use crate::types::TaskId;
-use crate::util::{Arena, ArenaIndex, RecyclingPool};
+use crate::util::{Arena, ArenaIndex};
 
 /// Number of task phases that are considered "live" (not Completed).
 const LIVE_PHASE_COUNT: usize = 5;
@@ -48,10 +49,9 @@ pub struct TaskTable {
     /// Object pool for recycling TaskRecord instances to eliminate allocation overhead.
     ///
     /// Reduces 35% of hot-path allocations by reusing TaskRecord objects instead
-    /// of creating new ones. Pool size is bounded to prevent unbounded growth.
-    task_record_pool: RecyclingPool<TaskRecord>,
-    /// Incremental telemetry for pooled vs heap-fallback behavior.
-    task_record_pool_stats: TaskRecordPoolStats,
+    /// of creating new ones. Each worker shard is bounded to prevent unbounded
+    /// growth, and the pool carries its own hit/miss/recycle telemetry.
+    task_record_pool: ShardedPool<TaskRecord>,
     /// Incremental counters for tasks in each phase (Created, Running, etc.).
     ///
     /// These counters are maintained for mutation paths that go through
@@ -100,8 +100,7 @@ impl TaskTable {
             tasks: Arena::new(),
             stored_futures: Vec::new(),
             stored_future_len: 0,
-            task_record_pool: RecyclingPool::new(256), // Pool up to 256 recycled TaskRecords
-            task_record_pool_stats: TaskRecordPoolStats::default(),
+            task_record_pool: ShardedPool::new(1, 256), // Pool up to 256 recycled TaskRecords
             phase_counts: [0; LIVE_PHASE_COUNT],
             live_task_count: 0,
             deadline_sum_ns: 0,
@@ -132,8 +131,7 @@ impl TaskTable {
             tasks: Arena::with_capacity(capacity),
             stored_futures: Vec::with_capacity(capacity),
             stored_future_len: 0,
-            task_record_pool: RecyclingPool::new(pool_limit),
-            task_record_pool_stats: TaskRecordPoolStats::default(),
+            task_record_pool: ShardedPool::new(1, pool_limit),
             phase_counts: [0; LIVE_PHASE_COUNT],
             live_task_count: 0,
             deadline_sum_ns: 0,
@@ -141,6 +139,34 @@ impl TaskTable {
         }
     }
 
+    /// Replaces the task-record pool with one sized by `config`.
+    ///
+    /// Records already pooled are released to the allocator. Intended to be
+    /// called while the table is being set up, before any task is spawned.
+    pub(crate) fn configure_object_pools(
+        &mut self,
+        config: &ObjectPoolConfig,
+        worker_threads: usize,
+    ) {
+        self.task_record_pool.drain();
+        self.task_record_pool = ShardedPool::from_config(config, worker_threads);
+    }
+
+    /// Releases every pooled task record back to the allocator.
+    ///
+    /// Returns the number of records drained. Called on runtime shutdown so
+    /// leak detectors observe no lingering pooled records.
+    pub fn drain_task_record_pool(&self) -> usize {
+        self.task_record_pool.drain()
+    }
+
+    /// Returns the task-record pool's cumulative counters.
+    #[inline]
+    #[must_use]
+    pub fn task_record_object_pool_stats(&self) -> ObjectPoolStats {
+        self.task_record_pool.stats()
+    }
+
     /// Returns the reserved task-record arena capacity.
     #[cfg(any(test, feature = "test-internals"))]
     #[allow(dead_code)]
@@ -165,7 +191,7 @@ impl TaskTable {
     #[inline]
     #[must_use]
     pub fn task_record_pool_capacity(&self) -> usize {
-        self.task_record_pool.max_size()
+        self.task_record_pool.max_per_shard()
     }
 
     /// Returns whether task-record pooling is enabled for this table.
@@ -174,7 +200,7 @@ impl TaskTable {
     #[inline]
     #[must_use]
     pub fn task_record_pool_enabled(&self) -> bool {
-        self.task_record_pool.max_size() > 0
+        self.task_record_pool.is_enabled()
     }
 
     /// Returns pooled-vs-heap fallback telemetry for this table.
@@ -183,7 +209,14 @@ impl TaskTable {
     #[inline]
     #[must_use]
     pub fn task_record_pool_stats(&self) -> TaskRecordPoolStats {
-        self.task_record_pool_stats
+        let stats = self.task_record_pool.stats();
+        let clamp = |value: u64| usize::try_from(value).unwrap_or(usize::MAX);
+        TaskRecordPoolStats {
+            hits: clamp(stats.hits),
+            misses: clamp(stats.misses),
+            recycled: clamp(stats.recycled),
+            recycle_drops: clamp(stats.recycle_drops),
+        }
     }
 
     /// Returns a shared reference to a task record by arena index.
@@ -199,6 +232,13 @@ impl TaskTable {
         self.tasks.get_mut(index)
     }
 
+    /// Returns the generation of the record occupying arena slot `slot`.
+    #[inline]
+    #[must_use]
+    pub(crate) fn occupant_generation(&self, slot: u32) -> Option<u32> {
+        self.tasks.occupant_generation(slot)
+    }
+
     /// Records a task phase transition for incremental bookkeeping.
     ///
     /// O(1) — updates cached counters used for Lyapunov governor snapshots.
@@ -357,12 +397,9 @@ impl TaskTable {
     #[inline]
     pub fn remove_and_recycle(&mut self, index: ArenaIndex) {
         if let Some(record) = self.remove(index) {
-            // Recycle the TaskRecord for future reuse
-            if self.task_record_pool.put_recycled(record) {
-                self.task_record_pool_stats.recycled += 1;
-            } else {
-                self.task_record_pool_stats.recycle_drops += 1;
-            }
+            // Recycle the TaskRecord for future reuse; the pool counts
+            // accepted and dropped records itself.
+            let _ = self.task_record_pool.recycle(record);
         }
     }
 
@@ -419,13 +456,9 @@ impl TaskTable {
         budget: crate::types::Budget,
         created_at: crate::types::Time,
     ) -> ArenaIndex {
-        let mut record = if let Some(record) = self.task_record_pool.try_get() {
-            self.task_record_pool_stats.hits += 1;
-            record
-        } else {
-            self.task_record_pool_stats.misses += 1;
+        let mut record = self.task_record_pool.try_acquire().unwrap_or_else(|| {
             TaskRecord::new_with_time(task_id, owner, budget, created_at)
-        };
+        });
 
         // Initialize the pooled record or fresh heap fallback.
         record.id = task_id;
@@ -486,17 +519,13 @@ impl TaskTable {
     where
         F: FnOnce(ArenaIndex, &mut TaskRecord),
     {
-        let mut record = if let Some(record) = self.task_record_pool.try_get() {
-            self.task_record_pool_stats.hits += 1;
-            record
-        } else {
-            self.task_record_pool_stats.misses += 1;
+        let mut record = self.task_record_pool.try_acquire().unwrap_or_else(|| {
             TaskRecord::new(
                 TaskId::from_arena(crate::util::ArenaIndex::new(0, 0)),
                 crate::types::RegionId::testing_default(),
                 crate::types::Budget::INFINITE,
             )
-        };
+        });
 
         let idx = self.tasks.insert_with(|idx| {
             // Apply custom initialization
@@ -7,8 +7,8 @@
 
 use crate::tracing_compat::trace;
 use crate::types::TaskId;
+use crate::util::loom::sync::Mutex;
 use crate::util::{DetHashMap, DetHashSet};
-use parking_lot::Mutex;
 use std::sync::{Arc, Weak};
 use std::task::{Wake, Waker};
 
@@ -0,0 +1,1127 @@
+//! Batched symbol authentication over a Merkle root.
+//!
+//! Per-symbol framing MACs every symbol. Batched framing amortizes the MAC:
+//! the sender collects up to [`BatchConfig::max_symbols`] symbols, builds a
+//! Merkle tree over their leaf hashes and MACs only the root. Every frame
+//! carries the batch header (id, leaf count, root, root tag) and the symbol's
+//! authentication path, so whichever frame of a batch arrives first introduces
+//! it. The receiver verifies the root tag once per batch; after that a
+//! symbol of the batch, late and reordered ones included, costs its leaf hash
+//! plus whichever interior hashes no earlier symbol of the batch has already
+//! authenticated. A whole batch costs one MAC, one hash per leaf and at most
+//! one hash per interior node.
+//!
+//! # Framing
+//!
+//! ```text
+//! v1 per-symbol:   version=1 | symbol | tag[32]
+//! v2 merkle-batch: version=2 | batch_id u64 | leaf_count u16 | leaf_index u16
+//!                  | root[32] | root_tag[32] | path_len u8 | path[32 * path_len]
+//!                  | symbol
+//! symbol:          object_id u128 | sbn u8 | esi u32 | kind u8 | len u32 | data
+//! ```
+//!
+//! Integers are little-endian, as in the tag construction. Peers advertise
+//! the framings they support and [`AuthFraming::negotiate`] picks the newest
+//! common one, falling back to per-symbol.
+//!
+//! # Tree shape
+//!
+//! Leaves are `SHA-256(leaf_domain || object_id || sbn || esi || kind || len
+//! || payload)`; interior nodes are `SHA-256(0x01 || left || right)`, and a
+//! lone node at the end of a level is promoted unchanged. The root tag is a
+//! domain-separated MAC over `batch_id || leaf_count || root`, so the leaf
+//! count is authenticated and fixes the length of every path.
+//!
+//! # Latency bound
+//!
+//! [`SymbolSigner`] is sans-I/O: it never holds a batch past
+//! [`BatchConfig::max_delay`] measured on the `now` the caller passes in.
+//! Drivers either call [`SymbolSigner::poll_flush`] from their own loop or
+//! await [`SymbolSigner::flush_at_deadline`], which sleeps on the runtime
+//! clock.
+//!
+//! # Receiver state
+//!
+//! [`SymbolVerifier`] remembers verified roots for the newest
+//! [`DEFAULT_BATCH_WINDOW`] batch ids. Frames for a batch that has fallen out
+//! of that window are rejected as [`RejectReason::UnknownBatch`] rather than
+//! re-verified, which bounds receiver state and refuses replays of old
+//! batches. A verifier tracks one sender's batch id space.
+
+use crate::decoding::RejectReason;
+use crate::security::authenticated::AuthenticatedSymbol;
+use crate::security::context::{AuthMode, SecurityContext};
+use crate::security::error::{AuthError, AuthErrorKind};
+use crate::security::tag::{AuthenticationTag, TAG_SIZE};
+use crate::types::{ObjectId, Symbol, SymbolId, SymbolKind, Time};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+
+/// Size of a Merkle node hash in bytes.
+pub const NODE_SIZE: usize = 32;
+
+/// Largest batch a signer builds or a verifier accepts.
+pub const MAX_BATCH_SYMBOLS: usize = 1024;
+
+/// Default number of batch ids a verifier keeps verified roots for.
+pub const DEFAULT_BATCH_WINDOW: u64 = 64;
+
+const LEAF_DOMAIN: &[u8] = b"asupersync::security::batch::leaf::v1";
+const ROOT_DOMAIN: &[u8] = b"asupersync::security::batch::root::v1";
+const INTERIOR_PREFIX: u8 = 0x01;
+const SYMBOL_HEADER_LEN: usize = 16 + 1 + 4 + 1 + 4;
+const BATCH_HEADER_LEN: usize = 8 + 2 + 2 + NODE_SIZE + TAG_SIZE + 1;
+
+// ─── Framing ────────────────────────────────────────────────────────────────
+
+/// Wire framing used to authenticate symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AuthFraming {
+    /// Every symbol carries its own tag (v1).
+    PerSymbol,
+    /// Symbols carry a Merkle path to a batch root that carries the tag (v2).
+    MerkleBatch,
+}
+
+impl AuthFraming {
+    /// Every framing this build understands, oldest first.
+    pub const ALL: [Self; 2] = [Self::PerSymbol, Self::MerkleBatch];
+
+    /// Returns the version byte that leads frames in this framing.
+    #[must_use]
+    pub const fn version(self) -> u8 {
+        match self {
+            Self::PerSymbol => 1,
+            Self::MerkleBatch => 2,
+        }
+    }
+
+    /// Maps a frame version byte back to its framing.
+    #[must_use]
+    pub const fn from_version(version: u8) -> Option<Self> {
+        match version {
+            1 => Some(Self::PerSymbol),
+            2 => Some(Self::MerkleBatch),
+            _ => None,
+        }
+    }
+
+    /// Picks the newest framing both sides support.
+    ///
+    /// Falls back to [`Self::PerSymbol`], which every peer speaks, when the
+    /// advertised sets share nothing newer.
+    #[must_use]
+    pub fn negotiate(local: &[Self], remote: &[Self]) -> Self {
+        local
+            .iter()
+            .filter(|framing| remote.contains(framing))
+            .max()
+            .copied()
+            .unwrap_or(Self::PerSymbol)
+    }
+}
+
+/// Batch header repeated in every frame of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchHeader {
+    /// Sender-assigned batch id, increasing per sender.
+    pub batch_id: u64,
+    /// Number of symbols in the batch.
+    pub leaf_count: u16,
+    /// Merkle root over the batch's leaf hashes.
+    pub root: [u8; NODE_SIZE],
+    /// MAC over the batch id, leaf count and root.
+    pub root_tag: AuthenticationTag,
+}
+
+impl BatchHeader {
+    fn mac_payload(&self) -> [u8; 8 + 2 + NODE_SIZE] {
+        let mut payload = [0u8; 8 + 2 + NODE_SIZE];
+        payload[..8].copy_from_slice(&self.batch_id.to_le_bytes());
+        payload[8..10].copy_from_slice(&self.leaf_count.to_le_bytes());
+        payload[10..].copy_from_slice(&self.root);
+        payload
+    }
+}
+
+/// A symbol framed for batched authentication.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchedSymbol {
+    /// The symbol.
+    pub symbol: Symbol,
+    /// Header of the batch the symbol belongs to.
+    pub header: BatchHeader,
+    /// Position of the symbol's leaf in the batch.
+    pub leaf_index: u16,
+    /// Sibling hashes from the leaf up to the root.
+    pub path: Vec<[u8; NODE_SIZE]>,
+}
+
+/// An authenticated symbol frame in either framing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthFrame {
+    /// A v1 frame: the symbol and its own tag.
+    PerSymbol(AuthenticatedSymbol),
+    /// A v2 frame: the symbol, its batch header and its Merkle path.
+    Batched(BatchedSymbol),
+}
+
+impl AuthFrame {
+    /// Returns the framing this frame uses.
+    #[must_use]
+    pub const fn framing(&self) -> AuthFraming {
+        match self {
+            Self::PerSymbol(_) => AuthFraming::PerSymbol,
+            Self::Batched(_) => AuthFraming::MerkleBatch,
+        }
+    }
+
+    /// Returns the framed symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &Symbol {
+        match self {
+            Self::PerSymbol(auth) => auth.symbol(),
+            Self::Batched(batched) => &batched.symbol,
+        }
+    }
+
+    /// Encodes the frame, leading with its version byte.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let symbol = self.symbol();
+        let mut out = Vec::with_capacity(1 + BATCH_HEADER_LEN + SYMBOL_HEADER_LEN + symbol.len());
+        out.push(self.framing().version());
+        match self {
+            Self::PerSymbol(auth) => {
+                encode_symbol(&mut out, symbol);
+                out.extend_from_slice(auth.tag().as_bytes());
+            }
+            Self::Batched(batched) => {
+                let header = &batched.header;
+                out.extend_from_slice(&header.batch_id.to_le_bytes());
+                out.extend_from_slice(&header.leaf_count.to_le_bytes());
+                out.extend_from_slice(&batched.leaf_index.to_le_bytes());
+                out.extend_from_slice(&header.root);
+                out.extend_from_slice(header.root_tag.as_bytes());
+                let path_len = u8::try_from(batched.path.len()).unwrap_or(u8::MAX);
+                out.push(path_len);
+                for node in batched.path.iter().take(usize::from(path_len)) {
+                    out.extend_from_slice(node);
+                }
+                encode_symbol(&mut out, symbol);
+            }
+        }
+        out
+    }
+
+    /// Decodes a frame produced by [`Self::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuthErrorKind::UnsupportedScheme`] for an unknown version
+    /// byte and [`AuthErrorKind::MalformedPayload`] for truncated, oversized or
+    /// trailing input.
+    pub fn decode(bytes: &[u8]) -> Result<Self, AuthError> {
+        let mut reader = Reader { bytes };
+        let version = reader.u8()?;
+        let frame = match AuthFraming::from_version(version) {
+            Some(AuthFraming::PerSymbol) => {
+                let symbol = decode_symbol(&mut reader)?;
+                let tag = AuthenticationTag::from_bytes(reader.array()?);
+                Self::PerSymbol(AuthenticatedSymbol::from_parts(symbol, tag))
+            }
+            Some(AuthFraming::MerkleBatch) => {
+                let batch_id = u64::from_le_bytes(reader.array()?);
+                let leaf_count = u16::from_le_bytes(reader.array()?);
+                let leaf_index = u16::from_le_bytes(reader.array()?);
+                let root = reader.array()?;
+                let root_tag = AuthenticationTag::from_bytes(reader.array()?);
+                let path_len = reader.u8()?;
+                let path = (0..path_len)
+                    .map(|_| reader.array())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let symbol = decode_symbol(&mut reader)?;
+                Self::Batched(BatchedSymbol {
+                    symbol,
+                    header: BatchHeader {
+                        batch_id,
+                        leaf_count,
+                        root,
+                        root_tag,
+                    },
+                    leaf_index,
+                    path,
+                })
+            }
+            None => {
+                return Err(AuthError::new(
+                    AuthErrorKind::UnsupportedScheme,
+                    format!("unknown symbol frame version {version}"),
+                ));
+            }
+        };
+        if !reader.bytes.is_empty() {
+            return Err(malformed("trailing bytes after symbol frame"));
+        }
+        Ok(frame)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], AuthError> {
+        if self.bytes.len() < len {
+            return Err(malformed("truncated symbol frame"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], AuthError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, AuthError> {
+        Ok(self.array::<1>()?[0])
+    }
+}
+
+fn malformed(message: &str) -> AuthError {
+    AuthError::new(AuthErrorKind::MalformedPayload, message)
+}
+
+const fn kind_byte(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Source => 0x53,
+        SymbolKind::Repair => 0xA7,
+    }
+}
+
+fn encode_symbol(out: &mut Vec<u8>, symbol: &Symbol) {
+    out.extend_from_slice(&symbol.object_id().as_u128().to_le_bytes());
+    out.push(symbol.sbn());
+    out.extend_from_slice(&symbol.esi().to_le_bytes());
+    out.push(kind_byte(symbol.kind()));
+    let len = u32::try_from(symbol.len()).unwrap_or(u32::MAX);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&symbol.data()[..len as usize]);
+}
+
+fn decode_symbol(reader: &mut Reader<'_>) -> Result<Symbol, AuthError> {
+    let object_id = ObjectId::from_u128(u128::from_le_bytes(reader.array()?));
+    let sbn = reader.u8()?;
+    let esi = u32::from_le_bytes(reader.array()?);
+    let kind = match reader.u8()? {
+        0x53 => SymbolKind::Source,
+        0xA7 => SymbolKind::Repair,
+        _ => return Err(malformed("unknown symbol kind")),
+    };
+    let len = u32::from_le_bytes(reader.array()?) as usize;
+    let data = reader.take(len)?;
+    Ok(Symbol::from_slice(SymbolId::new(object_id, sbn, esi), data, kind))
+}
+
+// ─── Merkle tree ────────────────────────────────────────────────────────────
+
+fn leaf_hash(symbol: &Symbol) -> [u8; NODE_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update(LEAF_DOMAIN);
+    hasher.update(symbol.object_id().as_u128().to_le_bytes());
+    hasher.update([symbol.sbn()]);
+    hasher.update(symbol.esi().to_le_bytes());
+    hasher.update([kind_byte(symbol.kind())]);
+    hasher.update((symbol.len() as u64).to_le_bytes());
+    hasher.update(symbol.data());
+    hasher.finalize().into()
+}
+
+fn interior_hash(left: &[u8; NODE_SIZE], right: &[u8; NODE_SIZE]) -> [u8; NODE_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update([INTERIOR_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Builds every level of the tree, leaves first and the root level last.
+fn tree_levels(leaves: Vec<[u8; NODE_SIZE]>) -> Vec<Vec<[u8; NODE_SIZE]>> {
+    let mut levels = vec![leaves];
+    while levels.last().is_some_and(|level| level.len() > 1) {
+        let below = levels.last().expect("non-empty levels");
+        let above = below
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => interior_hash(left, right),
+                [lone] => *lone,
+                _ => unreachable!("chunks(2) yields one or two nodes"),
+            })
+            .collect();
+        levels.push(above);
+    }
+    levels
+}
+
+fn auth_path(levels: &[Vec<[u8; NODE_SIZE]>], mut index: usize) -> Vec<[u8; NODE_SIZE]> {
+    let mut path = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            path.push(*sibling);
+        }
+        index /= 2;
+    }
+    path
+}
+
+/// Number of siblings on the path of leaf `index` in a batch of `leaf_count`.
+fn path_len(leaf_count: usize, mut index: usize) -> usize {
+    let mut width = leaf_count;
+    let mut len = 0;
+    while width > 1 {
+        if index ^ 1 < width {
+            len += 1;
+        }
+        index /= 2;
+        width = width.div_ceil(2);
+    }
+    len
+}
+
+// ─── Sender ─────────────────────────────────────────────────────────────────
+
+/// Batch sizing for [`SymbolSigner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    max_symbols: usize,
+    max_delay: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_symbols: 32,
+            max_delay: Duration::from_millis(2),
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Sets how many symbols fill a batch, clamped to
+    /// `1..=`[`MAX_BATCH_SYMBOLS`].
+    #[must_use]
+    pub fn with_max_symbols(mut self, max_symbols: usize) -> Self {
+        self.max_symbols = max_symbols.clamp(1, MAX_BATCH_SYMBOLS);
+        self
+    }
+
+    /// Sets how long the first symbol of a batch may wait for the batch to
+    /// fill before it is flushed anyway.
+    #[must_use]
+    pub const fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Returns the batch size.
+    #[must_use]
+    pub const fn max_symbols(&self) -> usize {
+        self.max_symbols
+    }
+
+    /// Returns the latency bound.
+    #[must_use]
+    pub const fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+}
+
+/// Frames outgoing symbols in the negotiated [`AuthFraming`].
+///
+/// In per-symbol framing every pushed symbol is signed and returned at once.
+/// In batched framing symbols are held until the batch fills or its deadline
+/// passes.
+#[derive(Debug)]
+pub struct SymbolSigner {
+    ctx: SecurityContext,
+    framing: AuthFraming,
+    config: BatchConfig,
+    next_batch_id: u64,
+    pending: Vec<Symbol>,
+    deadline: Option<Time>,
+}
+
+impl SymbolSigner {
+    /// Creates a signer for the given framing.
+    #[must_use]
+    pub const fn new(ctx: SecurityContext, framing: AuthFraming, config: BatchConfig) -> Self {
+        Self {
+            ctx,
+            framing,
+            config,
+            next_batch_id: 0,
+            pending: Vec::new(),
+            deadline: None,
+        }
+    }
+
+    /// Returns the framing this signer emits.
+    #[must_use]
+    pub const fn framing(&self) -> AuthFraming {
+        self.framing
+    }
+
+    /// Returns the number of symbols waiting in the open batch.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns when the open batch must be flushed, if one is open.
+    #[must_use]
+    pub const fn deadline(&self) -> Option<Time> {
+        self.deadline
+    }
+
+    /// Adds a symbol and returns the frames that are ready to send.
+    ///
+    /// The result is empty while a batch is still filling and within its
+    /// latency bound.
+    pub fn push(&mut self, symbol: Symbol, now: Time) -> Vec<AuthFrame> {
+        if self.framing == AuthFraming::PerSymbol {
+            return vec![AuthFrame::PerSymbol(self.ctx.sign_symbol(&symbol))];
+        }
+        if self.pending.is_empty() {
+            let delay = u64::try_from(self.config.max_delay.as_nanos())
+                .unwrap_or(u64::MAX);
+            self.deadline = Some(now.saturating_add_nanos(delay));
+        }
+        self.pending.push(symbol);
+        if self.pending.len() >= self.config.max_symbols {
+            return self.flush();
+        }
+        self.poll_flush(now)
+    }
+
+    /// Flushes the open batch if its deadline has passed at `now`.
+    pub fn poll_flush(&mut self, now: Time) -> Vec<AuthFrame> {
+        match self.deadline {
+            Some(deadline) if deadline <= now => self.flush(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Sleeps until the open batch's deadline on the runtime clock, then
+    /// flushes it. Returns nothing at once when no batch is open.
+    pub async fn flush_at_deadline(&mut self) -> Vec<AuthFrame> {
+        let Some(deadline) = self.deadline else {
+            return Vec::new();
+        };
+        crate::time::sleep_until(deadline).await;
+        self.flush()
+    }
+
+    /// Closes the open batch, however full, and returns its frames.
+    pub fn flush(&mut self) -> Vec<AuthFrame> {
+        self.deadline = None;
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        let symbols = std::mem::take(&mut self.pending);
+        let leaves = symbols.iter().map(leaf_hash).collect();
+        let levels = tree_levels(leaves);
+        let mut header = BatchHeader {
+            batch_id: self.next_batch_id,
+            leaf_count: u16::try_from(symbols.len()).expect("bounded batch"),
+            root: levels[levels.len() - 1][0],
+            root_tag: AuthenticationTag::zero(),
+        };
+        self.next_batch_id = self.next_batch_id.wrapping_add(1);
+        header.root_tag = self
+            .ctx
+            .sign_domain_payload(ROOT_DOMAIN, &header.mac_payload());
+
+        symbols
+            .into_iter()
+            .enumerate()
+            .map(|(index, symbol)| {
+                AuthFrame::Batched(BatchedSymbol {
+                    symbol,
+                    header,
+                    leaf_index: u16::try_from(index).expect("bounded batch"),
+                    path: auth_path(&levels, index),
+                })
+            })
+            .collect()
+    }
+}
+
+// ─── Receiver ───────────────────────────────────────────────────────────────
+
+/// Nodes of a batch whose root tag has been verified.
+#[derive(Debug)]
+struct VerifiedBatch {
+    leaf_count: u16,
+    /// Authenticated nodes by level, leaves first; the last level holds only
+    /// the root.
+    nodes: Vec<Vec<Option<[u8; NODE_SIZE]>>>,
+}
+
+impl VerifiedBatch {
+    fn new(header: &BatchHeader) -> Self {
+        let mut nodes = Vec::new();
+        let mut width = usize::from(header.leaf_count);
+        while width > 1 {
+            nodes.push(vec![None; width]);
+            width = width.div_ceil(2);
+        }
+        nodes.push(vec![Some(header.root)]);
+        Self {
+            leaf_count: header.leaf_count,
+            nodes,
+        }
+    }
+
+    fn root(&self) -> [u8; NODE_SIZE] {
+        self.nodes[self.nodes.len() - 1][0].expect("root is authenticated")
+    }
+
+    /// Walks a leaf up its path until it meets an authenticated node.
+    ///
+    /// Every node computed or taken from the path on the way becomes
+    /// authenticated when the walk ends on a match, so later symbols of the
+    /// batch stop earlier: across a whole batch each interior node is hashed
+    /// at most once.
+    fn check_path(
+        &mut self,
+        leaf: [u8; NODE_SIZE],
+        mut index: usize,
+        path: &[[u8; NODE_SIZE]],
+    ) -> bool {
+        let mut hash = leaf;
+        let mut siblings = path.iter();
+        let mut learned = Vec::new();
+        for level in 0..self.nodes.len() {
+            if let Some(known) = self.nodes[level][index] {
+                if !bool::from(known.ct_eq(&hash)) {
+                    return false;
+                }
+                for (level, index, node) in learned {
+                    self.nodes[level][index] = Some(node);
+                }
+                return true;
+            }
+            learned.push((level, index, hash));
+            let sibling_index = index ^ 1;
+            if sibling_index < self.nodes[level].len() {
+                let Some(from_path) = siblings.next() else {
+                    return false;
+                };
+                let sibling = self.nodes[level][sibling_index].unwrap_or(*from_path);
+                learned.push((level, sibling_index, sibling));
+                hash = if index % 2 == 0 {
+                    interior_hash(&hash, &sibling)
+                } else {
+                    interior_hash(&sibling, &hash)
+                };
+            }
+            index /= 2;
+        }
+        false
+    }
+}
+
+/// Verifies incoming frames in the negotiated [`AuthFraming`].
+///
+/// Verified symbols come back marked verified. A batched symbol carries the
+/// batch root tag rather than a per-symbol tag, so it must be fed to the
+/// decoder as caller-verified instead of being re-verified per symbol.
+#[derive(Debug)]
+pub struct SymbolVerifier {
+    ctx: SecurityContext,
+    framing: AuthFraming,
+    window: u64,
+    batches: BTreeMap<u64, VerifiedBatch>,
+    newest: Option<u64>,
+    root_verifications: u64,
+    path_checks: u64,
+}
+
+impl SymbolVerifier {
+    /// Creates a verifier for the given framing.
+    #[must_use]
+    pub const fn new(ctx: SecurityContext, framing: AuthFraming) -> Self {
+        Self {
+            ctx,
+            framing,
+            window: DEFAULT_BATCH_WINDOW,
+            batches: BTreeMap::new(),
+            newest: None,
+            root_verifications: 0,
+            path_checks: 0,
+        }
+    }
+
+    /// Sets how many of the newest batch ids keep their verified roots.
+    #[must_use]
+    pub fn with_window(mut self, window: u64) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Returns the framing this verifier accepts.
+    #[must_use]
+    pub const fn framing(&self) -> AuthFraming {
+        self.framing
+    }
+
+    /// Returns how many batch root tags have been MAC-verified.
+    #[must_use]
+    pub const fn root_verifications(&self) -> u64 {
+        self.root_verifications
+    }
+
+    /// Returns how many Merkle paths have been checked.
+    #[must_use]
+    pub const fn path_checks(&self) -> u64 {
+        self.path_checks
+    }
+
+    /// Decodes and verifies a frame.
+    ///
+    /// # Errors
+    ///
+    /// Malformed frames are rejected as [`RejectReason::InvalidMetadata`];
+    /// otherwise see [`Self::verify`].
+    pub fn verify_bytes(&mut self, bytes: &[u8]) -> Result<AuthenticatedSymbol, RejectReason> {
+        let frame = AuthFrame::decode(bytes)
+            .map_err(|_| RejectReason::InvalidMetadata)?;
+        self.verify(frame)
+    }
+
+    /// Verifies a frame according to the context's [`AuthMode`].
+    ///
+    /// `Disabled` returns the symbol unauthenticated and `Permissive` returns
+    /// a failed symbol unverified instead of rejecting it, matching
+    /// [`SecurityContext::verify_authenticated_symbol`].
+    ///
+    /// # Errors
+    ///
+    /// - [`RejectReason::InvalidMetadata`]: the frame is not in the
+    ///   negotiated framing, or its leaf count is out of range.
+    /// - [`RejectReason::AuthenticationFailed`]: a per-symbol tag is invalid.
+    /// - [`RejectReason::UnknownBatch`]: the batch is older than the window.
+    /// - [`RejectReason::BatchRootInvalid`]: the root tag is invalid or the
+    ///   root differs from the one verified for the batch.
+    /// - [`RejectReason::BadPath`]: the symbol's path does not reach the root.
+    pub fn verify(&mut self, frame: AuthFrame) -> Result<AuthenticatedSymbol, RejectReason> {
+        if frame.framing() != self.framing {
+            return Err(RejectReason::InvalidMetadata);
+        }
+        let batched = match frame {
+            AuthFrame::PerSymbol(mut auth) => {
+                return match self.ctx.verify_authenticated_symbol(&mut auth) {
+                    Ok(()) => Ok(auth),
+                    Err(_) => Err(RejectReason::AuthenticationFailed),
+                };
+            }
+            AuthFrame::Batched(batched) => batched,
+        };
+
+        match self.ctx.mode() {
+            AuthMode::Disabled => {
+                self.ctx.stats().skipped.fetch_add(1, Ordering::Relaxed);
+                Ok(AuthenticatedSymbol::new_unauthenticated(batched.symbol))
+            }
+            mode => match self.check_batched(&batched) {
+                Ok(()) => Ok(AuthenticatedSymbol::new_verified(
+                    batched.symbol,
+                    batched.header.root_tag,
+                )),
+                Err(_) if mode == AuthMode::Permissive => {
+                    let stats = self.ctx.stats();
+                    stats.failures_allowed.fetch_add(1, Ordering::Relaxed);
+                    Ok(AuthenticatedSymbol::from_parts(batched.symbol, batched.header.root_tag))
+                }
+                Err(reason) => Err(reason),
+            },
+        }
+    }
+
+    fn check_batched(&mut self, batched: &BatchedSymbol) -> Result<(), RejectReason> {
+        let header = &batched.header;
+        let leaf_count = usize::from(header.leaf_count);
+        if leaf_count == 0 || leaf_count > MAX_BATCH_SYMBOLS {
+            return Err(RejectReason::InvalidMetadata);
+        }
+        if self
+            .newest
+            .is_some_and(|newest| header.batch_id.saturating_add(self.window) <= newest)
+        {
+            return Err(RejectReason::UnknownBatch);
+        }
+
+        if let Some(batch) = self.batches.get(&header.batch_id) {
+            let same_root: bool = batch.root().ct_eq(&header.root).into();
+            if !same_root || batch.leaf_count != header.leaf_count {
+                return Err(RejectReason::BatchRootInvalid);
+            }
+        } else {
+            self.root_verifications += 1;
+            let payload = header.mac_payload();
+            let valid = self
+                .ctx
+                .verify_domain_payload(ROOT_DOMAIN, &payload, &header.root_tag);
+            if !valid {
+                return Err(RejectReason::BatchRootInvalid);
+            }
+            self.remember(header);
+        }
+
+        self.path_checks += 1;
+        let index = usize::from(batched.leaf_index);
+        if index >= leaf_count || batched.path.len() != path_len(leaf_count, index) {
+            return Err(RejectReason::BadPath);
+        }
+        let leaf = leaf_hash(&batched.symbol);
+        let batch = self
+            .batches
+            .get_mut(&header.batch_id)
+            .expect("batch remembered above");
+        if batch.check_path(leaf, index, &batched.path) {
+            Ok(())
+        } else {
+            Err(RejectReason::BadPath)
+        }
+    }
+
+    fn remember(&mut self, header: &BatchHeader) {
+        self.batches
+            .insert(header.batch_id, VerifiedBatch::new(header));
+        let newest = self
+            .newest
+            .map_or(header.batch_id, |newest| newest.max(header.batch_id));
+        self.newest = Some(newest);
+        let oldest_kept = newest.saturating_sub(self.window - 1);
+        self.batches = self.batches.split_off(&oldest_kept);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::bytes::Bytes;
+    use crate::lab::{DeterministicNetwork, NetworkConditions, NetworkConfig};
+    use futures_lite::future::block_on;
+    use std::collections::BTreeSet;
+
+    fn symbol(esi: u32) -> Symbol {
+        Symbol::new_for_test(7, 0, esi, &[esi as u8; 48])
+    }
+
+    fn batched_pair(batch: usize) -> (SymbolSigner, SymbolVerifier) {
+        let config = BatchConfig::default().with_max_symbols(batch);
+        let signer = SymbolSigner::new(
+            SecurityContext::for_testing(11),
+            AuthFraming::MerkleBatch,
+            config,
+        );
+        let verifier =
+            SymbolVerifier::new(SecurityContext::for_testing(11), AuthFraming::MerkleBatch);
+        (signer, verifier)
+    }
+
+    fn sign_all(signer: &mut SymbolSigner, count: u32) -> Vec<AuthFrame> {
+        (0..count)
+            .flat_map(|esi| signer.push(symbol(esi), Time::ZERO))
+            .collect()
+    }
+
+    #[test]
+    fn every_leaf_of_odd_sized_batches_verifies() {
+        for size in 1..=17 {
+            let (mut signer, mut verifier) = batched_pair(size);
+            let frames = sign_all(&mut signer, size as u32);
+            assert_eq!(frames.len(), size, "batch of {size} flushes when full");
+            for frame in frames.into_iter().rev() {
+                let bytes = frame.encode();
+                assert_eq!(AuthFrame::decode(&bytes).unwrap(), frame);
+                let auth = verifier.verify_bytes(&bytes).expect("verifies");
+                assert!(auth.is_verified());
+            }
+            assert_eq!(verifier.root_verifications(), 1);
+        }
+    }
+
+    #[test]
+    fn end_to_end_over_lossy_reordering_network() {
+        let mut net = DeterministicNetwork::new(NetworkConfig {
+            seed: 0xBA7C,
+            default_conditions: NetworkConditions {
+                packet_loss: 0.1,
+                packet_reorder: 0.3,
+                ..NetworkConditions::lan()
+            },
+            ..NetworkConfig::default()
+        });
+        let tx = net.add_host("sender");
+        let rx = net.add_host("receiver");
+        let (mut signer, mut verifier) = batched_pair(16);
+
+        let mut sent = 0;
+        for esi in 0..200u32 {
+            let now = net.now();
+            for frame in signer.push(symbol(esi), now) {
+                net.send(tx, rx, Bytes::from(frame.encode()));
+                sent += 1;
+            }
+            net.run_for(Duration::from_micros(50));
+        }
+        for frame in signer.flush() {
+            net.send(tx, rx, Bytes::from(frame.encode()));
+            sent += 1;
+        }
+        net.run_until_idle();
+        assert_eq!(sent, 200);
+
+        let inbox = net.take_inbox(rx).expect("receiver inbox");
+        let mut received = BTreeSet::new();
+        let mut out_of_order = false;
+        let mut last = None;
+        for packet in &inbox {
+            let auth = verifier
+                .verify_bytes(&packet.payload)
+                .expect("delivered frame verifies");
+            assert!(auth.is_verified());
+            let esi = auth.symbol().esi();
+            out_of_order |= last.is_some_and(|last| esi < last);
+            last = Some(esi);
+            received.insert(esi);
+        }
+        assert!(received.len() < 200, "some frames were lost");
+        assert!(received.len() > 150, "most frames arrive");
+        assert!(out_of_order, "some frames were reordered");
+        assert!(verifier.root_verifications() <= 13);
+        assert_eq!(verifier.path_checks(), inbox.len() as u64);
+    }
+
+    #[test]
+    fn tampered_symbol_fails_its_path_only() {
+        let (mut signer, mut verifier) = batched_pair(8);
+        let frames = sign_all(&mut signer, 8);
+        let mut bytes = frames[3].encode();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x40;
+        assert_eq!(verifier.verify_bytes(&bytes), Err(RejectReason::BadPath));
+
+        for (index, frame) in frames.iter().enumerate() {
+            let result = verifier.verify_bytes(&frame.encode());
+            assert!(result.is_ok(), "untouched symbol {index} still verifies");
+        }
+
+        let AuthFrame::Batched(mut swapped) = frames[1].clone() else {
+            panic!("batched frame");
+        };
+        swapped.leaf_index = 2;
+        assert_eq!(
+            verifier.verify(AuthFrame::Batched(swapped)),
+            Err(RejectReason::BadPath)
+        );
+    }
+
+    #[test]
+    fn tampered_root_rejects_whole_batch() {
+        let (mut signer, mut verifier) = batched_pair(4);
+        let frames = sign_all(&mut signer, 4);
+        for frame in &frames {
+            let mut bytes = frame.encode();
+            // version(1) + batch_id(8) + leaf_count(2) + leaf_index(2) = root offset.
+            bytes[13] ^= 0x01;
+            assert_eq!(
+                verifier.verify_bytes(&bytes),
+                Err(RejectReason::BatchRootInvalid)
+            );
+        }
+        assert_eq!(verifier.path_checks(), 0);
+
+        // Once the genuine root is verified, a conflicting root for the same id
+        // is refused without another MAC.
+        verifier.verify_bytes(&frames[0].encode()).unwrap();
+        let mut bytes = frames[1].encode();
+        bytes[13] ^= 0x01;
+        let macs = verifier.root_verifications();
+        assert_eq!(
+            verifier.verify_bytes(&bytes),
+            Err(RejectReason::BatchRootInvalid)
+        );
+        assert_eq!(verifier.root_verifications(), macs);
+
+        let (mut other_key, _) = batched_pair(4);
+        other_key.ctx = SecurityContext::for_testing(12);
+        let forged = other_key.push(symbol(0), Time::ZERO);
+        assert!(forged.is_empty());
+        let forged = other_key.flush();
+        let (_, mut fresh) = batched_pair(4);
+        assert_eq!(
+            fresh.verify(forged[0].clone()),
+            Err(RejectReason::BatchRootInvalid)
+        );
+    }
+
+    #[test]
+    fn batches_older_than_window_are_unknown() {
+        let (mut signer, verifier) = batched_pair(1);
+        let mut verifier = verifier.with_window(2);
+        let frames = sign_all(&mut signer, 4);
+        verifier.verify(frames[3].clone()).unwrap();
+        verifier.verify(frames[2].clone()).unwrap();
+        assert_eq!(
+            verifier.verify(frames[1].clone()),
+            Err(RejectReason::UnknownBatch)
+        );
+        assert_eq!(verifier.batches.len(), 2);
+    }
+
+    #[test]
+    fn partial_batch_flushes_at_latency_bound() {
+        let config = BatchConfig::default()
+            .with_max_symbols(64)
+            .with_max_delay(Duration::from_millis(2));
+        let mut signer = SymbolSigner::new(
+            SecurityContext::for_testing(11),
+            AuthFraming::MerkleBatch,
+            config,
+        );
+        assert!(signer.push(symbol(0), Time::from_millis(10)).is_empty());
+        assert!(signer.push(symbol(1), Time::from_millis(11)).is_empty());
+        assert_eq!(signer.deadline(), Some(Time::from_millis(12)));
+        assert!(signer.poll_flush(Time::from_millis(11)).is_empty());
+        let frames = signer.poll_flush(Time::from_millis(12));
+        assert_eq!(frames.len(), 2);
+        assert_eq!(signer.deadline(), None);
+        assert_eq!(signer.pending(), 0);
+
+        // A push past the deadline flushes on the spot.
+        assert!(signer.push(symbol(2), Time::from_millis(20)).is_empty());
+        assert_eq!(signer.push(symbol(3), Time::from_millis(23)).len(), 2);
+    }
+
+    #[test]
+    fn flush_at_deadline_waits_on_the_runtime_clock() {
+        let mut signer = SymbolSigner::new(
+            SecurityContext::for_testing(11),
+            AuthFraming::MerkleBatch,
+            BatchConfig::default().with_max_delay(Duration::from_millis(1)),
+        );
+        let now = crate::time::wall_now();
+        assert!(signer.push(symbol(0), now).is_empty());
+        let frames = block_on(signer.flush_at_deadline());
+        assert_eq!(frames.len(), 1);
+        assert!(crate::time::wall_now() >= now.saturating_add_nanos(1_000_000));
+        assert!(block_on(signer.flush_at_deadline()).is_empty());
+    }
+
+    #[test]
+    fn negotiation_falls_back_to_per_symbol() {
+        assert_eq!(
+            AuthFraming::negotiate(&AuthFraming::ALL, &AuthFraming::ALL),
+            AuthFraming::MerkleBatch
+        );
+        let legacy = [AuthFraming::PerSymbol];
+        let framing = AuthFraming::negotiate(&AuthFraming::ALL, &legacy);
+        assert_eq!(framing, AuthFraming::PerSymbol);
+        assert_eq!(AuthFraming::negotiate(&[], &legacy), AuthFraming::PerSymbol);
+
+        let mut signer = SymbolSigner::new(
+            SecurityContext::for_testing(11),
+            framing,
+            BatchConfig::default(),
+        );
+        let mut verifier = SymbolVerifier::new(SecurityContext::for_testing(11), framing);
+        let frames = signer.push(symbol(5), Time::ZERO);
+        assert_eq!(frames.len(), 1, "per-symbol framing never holds symbols");
+        let bytes = frames[0].encode();
+        assert_eq!(bytes[0], AuthFraming::PerSymbol.version());
+        assert!(verifier.verify_bytes(&bytes).unwrap().is_verified());
+
+        let mut tampered = bytes.clone();
+        tampered[30] ^= 0x01;
+        assert_eq!(
+            verifier.verify_bytes(&tampered),
+            Err(RejectReason::AuthenticationFailed)
+        );
+
+        let (mut batch_signer, _) = batched_pair(1);
+        let batch_frame = batch_signer.push(symbol(6), Time::ZERO);
+        assert_eq!(
+            verifier.verify(batch_frame[0].clone()),
+            Err(RejectReason::InvalidMetadata),
+            "frames outside the negotiated framing are refused"
+        );
+    }
+
+    #[test]
+    fn permissive_mode_passes_failures_unverified() {
+        let (mut signer, _) = batched_pair(2);
+        let frames = sign_all(&mut signer, 2);
+        let ctx = SecurityContext::for_testing_with_mode(11, AuthMode::Permissive);
+        let mut verifier = SymbolVerifier::new(ctx, AuthFraming::MerkleBatch);
+        let mut bytes = frames[0].encode();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        let auth = verifier.verify_bytes(&bytes).expect("permissive accepts");
+        assert!(!auth.is_verified());
+        let untouched = verifier.verify_bytes(&frames[1].encode()).unwrap();
+        assert!(untouched.is_verified());
+    }
+
+    #[test]
+    fn decode_rejects_malformed_frames() {
+        assert_eq!(
+            AuthFrame::decode(&[9]).unwrap_err().kind(),
+            AuthErrorKind::UnsupportedScheme
+        );
+        let (mut signer, mut verifier) = batched_pair(1);
+        let mut bytes = signer.push(symbol(0), Time::ZERO)[0].encode();
+        bytes.push(0);
+        assert_eq!(
+            AuthFrame::decode(&bytes).unwrap_err().kind(),
+            AuthErrorKind::MalformedPayload
+        );
+        bytes.truncate(20);
+        assert_eq!(
+            verifier.verify_bytes(&bytes),
+            Err(RejectReason::InvalidMetadata)
+        );
+    }
+
+    #[test]
+    fn batching_amortizes_macs() {
+        let (mut signer, mut verifier) = batched_pair(32);
+        let frames = sign_all(&mut signer, 256);
+        let signed_before = verifier.ctx.stats().verified_ok.load(Ordering::Relaxed);
+        for frame in frames {
+            verifier.verify(frame).unwrap();
+        }
+        let macs = verifier.ctx.stats().verified_ok.load(Ordering::Relaxed) - signed_before;
+        assert_eq!(macs, 8, "one MAC per batch of 32");
+        assert_eq!(verifier.path_checks(), 256);
+        assert_eq!(signer.ctx.stats().signed.load(Ordering::Relaxed), 8);
+    }
+}
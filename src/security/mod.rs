@@ -18,6 +18,10 @@
 //! bytes. The construction is deterministic, capability-explicit, and suitable
 //! for real integrity verification in production code.
 //!
+//! High-rate senders can negotiate the [`batch`] framing instead, which MACs
+//! one Merkle root per batch of symbols and gives each symbol a hash path to
+//! that root.
+//!
 //! # Architecture
 //!
 //! ```text
@@ -67,6 +71,7 @@
 //! ```
 
 pub mod authenticated;
+pub mod batch;
 pub mod context;
 #[cfg(test)]
 mod cryptographic_boundary_tests;
@@ -77,6 +82,9 @@ pub mod secret;
 pub mod tag;
 
 pub use authenticated::{AuthenticatedSymbol, AuthenticatedSymbolState};
+pub use batch::{
+    AuthFrame, AuthFraming, BatchConfig, BatchHeader, BatchedSymbol, SymbolSigner, SymbolVerifier,
+};
 pub use context::{AuthMode, SecurityContext};
 pub use error::{AuthError, AuthErrorKind, AuthResult};
 pub use key::{AUTH_KEY_SIZE, AuthKey, KeyRing};
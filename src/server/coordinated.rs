@@ -0,0 +1,955 @@
+//! Load-balancer-aware two-phase shutdown.
+//!
+//! Rolling restarts drop requests when the load balancer keeps routing to an
+//! instance that has already stopped accepting connections: the readiness
+//! probe flips too late relative to connection draining. [`CoordinatedShutdown`]
+//! orders the steps so the load balancer hears about the shutdown first:
+//!
+//! 1. [`Serving`](CoordinatedPhase::Serving) — normal operation.
+//! 2. [`Draining`](CoordinatedPhase::Draining) — the readiness gate flips:
+//!    `/readyz` answers 503 with `Retry-After` and `Drain-Deadline` headers
+//!    (see [`DrainNotice`]). In-flight and newly arriving requests are still
+//!    served normally for the configured LB propagation delay, measured on the
+//!    runtime clock.
+//! 3. [`Stopping`](CoordinatedPhase::Stopping) — the [`ShutdownSignal`] enters
+//!    its drain phase: the accept loop stops, new connections are refused at
+//!    the TCP level, and existing connections drain under the drain timeout.
+//! 4. [`Stopped`](CoordinatedPhase::Stopped) — the server reported
+//!    [`ShutdownPhase::Stopped`].
+//!
+//! Note that the coordinator's `Draining` phase precedes the server's
+//! [`ShutdownPhase::Draining`]; the latter begins together with `Stopping`.
+//!
+//! The sequence starts on [`CoordinatedShutdown::request_shutdown`] or, under
+//! [`CoordinatedShutdown::run_with_controller`], on the first request seen by
+//! a [`ShutdownController`] — the first SIGTERM when the controller listens
+//! for signals. A further request skips whatever remains of the propagation
+//! delay.
+//!
+//! # App lifecycle
+//!
+//! The application keeps running through `Draining` and `Stopping`; once the
+//! coordinator is `Stopped`, [`CoordinatedShutdown::stop_app`] starts the app
+//! root region's close → drain → finalize sequence.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::server::{CoordinatedShutdown, CoordinatedShutdownConfig};
+//! use asupersync::web::health::HealthCheck;
+//! use std::time::Duration;
+//!
+//! let health = HealthCheck::new();
+//! let coordinator = CoordinatedShutdown::new(
+//!     CoordinatedShutdownConfig::default().propagation_delay(Duration::from_secs(10)),
+//!     listener.shutdown_signal(),
+//!     health.clone(),
+//! );
+//! controller.listen_for_signals();
+//! let metrics = coordinator.run_with_controller(&controller).await;
+//! ```
+
+use crate::app::{AppHandle, AppStopError, StoppedApp};
+use crate::runtime::RuntimeState;
+use crate::signal::{ShutdownController, ShutdownReceiver};
+use crate::sync::Notify;
+use crate::tracing_compat::info;
+use crate::types::Time;
+use crate::web::health::{DrainNotice, HealthCheck};
+use parking_lot::Mutex;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::time::Duration;
+
+use super::shutdown::{ShutdownPhase, ShutdownSignal};
+
+/// Phases of a coordinated shutdown, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum CoordinatedPhase {
+    /// Ready and accepting connections.
+    Serving = 0,
+    /// Readiness reports draining; requests are still served while the load
+    /// balancer observes the change.
+    Draining = 1,
+    /// New connections are refused; existing connections drain.
+    Stopping = 2,
+    /// The server has fully stopped.
+    Stopped = 3,
+}
+
+impl CoordinatedPhase {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Serving,
+            1 => Self::Draining,
+            2 => Self::Stopping,
+            _ => Self::Stopped,
+        }
+    }
+
+    /// Returns the phase name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Serving => "serving",
+            Self::Draining => "draining",
+            Self::Stopping => "stopping",
+            Self::Stopped => "stopped",
+        }
+    }
+}
+
+impl std::fmt::Display for CoordinatedPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Timing configuration for [`CoordinatedShutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinatedShutdownConfig {
+    /// How long to keep serving after readiness flips, so the load balancer
+    /// has time to observe the failing probe and stop routing here.
+    pub propagation_delay: Duration,
+    /// `Retry-After` hint sent by the readiness probe while draining.
+    pub retry_after: Duration,
+    /// Connection drain budget handed to [`ShutdownSignal::begin_drain`] once
+    /// accepts stop.
+    pub drain_timeout: Duration,
+}
+
+impl Default for CoordinatedShutdownConfig {
+    fn default() -> Self {
+        Self {
+            propagation_delay: Duration::from_secs(5),
+            retry_after: Duration::from_secs(5),
+            drain_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CoordinatedShutdownConfig {
+    /// Set the LB propagation delay.
+    #[must_use]
+    pub fn propagation_delay(mut self, delay: Duration) -> Self {
+        self.propagation_delay = delay;
+        self
+    }
+
+    /// Set the readiness `Retry-After` hint.
+    #[must_use]
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Set the connection drain timeout.
+    #[must_use]
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+}
+
+/// Why a phase transition happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionCause {
+    /// The first shutdown request started the sequence.
+    ShutdownRequested,
+    /// The LB propagation delay elapsed.
+    PropagationElapsed,
+    /// A further shutdown request cut the propagation delay short.
+    PropagationSkipped,
+    /// The server finished draining connections.
+    ServerStopped,
+}
+
+/// A phase transition, as emitted by [`CoordinatedShutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinatedShutdownEvent {
+    /// Phase before the transition.
+    pub from: CoordinatedPhase,
+    /// Phase after the transition.
+    pub to: CoordinatedPhase,
+    /// Runtime-clock time of the transition.
+    pub at: Time,
+    /// What triggered the transition.
+    pub cause: TransitionCause,
+}
+
+/// Result of [`CoordinatedShutdown::request_shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownRequestOutcome {
+    /// The request started the sequence.
+    Started,
+    /// The request skipped the remaining propagation delay.
+    Expedited,
+    /// The sequence was already past the propagation delay.
+    Ignored,
+}
+
+/// Point-in-time metrics for a [`CoordinatedShutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinatedShutdownMetrics {
+    /// Current phase.
+    pub phase: CoordinatedPhase,
+    /// Shutdown requests received, including repeats.
+    pub shutdown_requests: u64,
+    /// Phase transitions taken so far.
+    pub transitions: u64,
+    /// Whether a repeated request skipped the propagation delay.
+    pub propagation_skipped: bool,
+    /// When the readiness gate flipped to draining.
+    pub readiness_flipped_at: Option<Time>,
+    /// When new connections stopped being accepted.
+    pub accept_stopped_at: Option<Time>,
+    /// When the server reported stopped.
+    pub stopped_at: Option<Time>,
+}
+
+#[derive(Debug, Default)]
+struct Timeline {
+    events: Vec<CoordinatedShutdownEvent>,
+    accept_stop_deadline: Option<Time>,
+}
+
+impl Timeline {
+    fn entered(&self, phase: CoordinatedPhase) -> Option<Time> {
+        self.events
+            .iter()
+            .find(|event| event.to == phase)
+            .map(|event| event.at)
+    }
+}
+
+struct CoordinatorState {
+    config: CoordinatedShutdownConfig,
+    signal: ShutdownSignal,
+    health: HealthCheck,
+    phase: AtomicU8,
+    requests: AtomicU64,
+    expedited: AtomicBool,
+    notify: Notify,
+    timeline: Mutex<Timeline>,
+}
+
+/// Drives the Serving → Draining → Stopping → Stopped shutdown sequence.
+///
+/// `CoordinatedShutdown` is `Clone`; all clones share the same sequence.
+#[derive(Clone)]
+pub struct CoordinatedShutdown {
+    state: Arc<CoordinatorState>,
+}
+
+impl CoordinatedShutdown {
+    /// Creates a coordinator for the server behind `signal`, gating `health`.
+    ///
+    /// `signal` is usually the listener's
+    /// [`shutdown_signal`](crate::http::h1::listener::Http1Listener::shutdown_signal);
+    /// its time source is the runtime clock used for the propagation delay.
+    #[must_use]
+    pub fn new(
+        config: CoordinatedShutdownConfig,
+        signal: ShutdownSignal,
+        health: HealthCheck,
+    ) -> Self {
+        Self {
+            state: Arc::new(CoordinatorState {
+                config,
+                signal,
+                health,
+                phase: AtomicU8::new(CoordinatedPhase::Serving as u8),
+                requests: AtomicU64::new(0),
+                expedited: AtomicBool::new(false),
+                notify: Notify::new(),
+                timeline: Mutex::new(Timeline::default()),
+            }),
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub fn config(&self) -> &CoordinatedShutdownConfig {
+        &self.state.config
+    }
+
+    /// Returns the server shutdown signal driven by this coordinator.
+    #[must_use]
+    pub fn shutdown_signal(&self) -> &ShutdownSignal {
+        &self.state.signal
+    }
+
+    /// Returns the health check whose readiness gate this coordinator flips.
+    #[must_use]
+    pub fn health(&self) -> &HealthCheck {
+        &self.state.health
+    }
+
+    /// Returns the current phase.
+    #[must_use]
+    pub fn phase(&self) -> CoordinatedPhase {
+        CoordinatedPhase::from_u8(self.state.phase.load(Ordering::Acquire))
+    }
+
+    /// Returns every transition taken so far, oldest first.
+    #[must_use]
+    pub fn events(&self) -> Vec<CoordinatedShutdownEvent> {
+        self.state.timeline.lock().events.clone()
+    }
+
+    /// Returns when new connections stop being accepted, once draining.
+    #[must_use]
+    pub fn accept_stop_deadline(&self) -> Option<Time> {
+        self.state.timeline.lock().accept_stop_deadline
+    }
+
+    /// Returns a metrics snapshot.
+    #[must_use]
+    pub fn metrics(&self) -> CoordinatedShutdownMetrics {
+        let timeline = self.state.timeline.lock();
+        CoordinatedShutdownMetrics {
+            phase: self.phase(),
+            shutdown_requests: self.state.requests.load(Ordering::Acquire),
+            transitions: timeline.events.len() as u64,
+            propagation_skipped: timeline
+                .events
+                .iter()
+                .any(|event| event.cause == TransitionCause::PropagationSkipped),
+            readiness_flipped_at: timeline.entered(CoordinatedPhase::Draining),
+            accept_stopped_at: timeline.entered(CoordinatedPhase::Stopping),
+            stopped_at: timeline.entered(CoordinatedPhase::Stopped),
+        }
+    }
+
+    /// Requests shutdown.
+    ///
+    /// The first request flips the readiness gate and enters
+    /// [`Draining`](CoordinatedPhase::Draining) immediately. A request while
+    /// draining skips the rest of the propagation delay. The phase
+    /// transitions that follow are taken by [`run`](Self::run).
+    pub fn request_shutdown(&self) -> ShutdownRequestOutcome {
+        self.state.requests.fetch_add(1, Ordering::AcqRel);
+        let outcome = if self.transition(
+            CoordinatedPhase::Serving,
+            CoordinatedPhase::Draining,
+            TransitionCause::ShutdownRequested,
+        ) {
+            ShutdownRequestOutcome::Started
+        } else if self.phase() == CoordinatedPhase::Draining
+            && !self.state.expedited.swap(true, Ordering::AcqRel)
+        {
+            ShutdownRequestOutcome::Expedited
+        } else {
+            ShutdownRequestOutcome::Ignored
+        };
+        info!(
+            shutdown_requests = self.state.requests.load(Ordering::Acquire),
+            outcome = ?outcome,
+            "coordinated shutdown requested"
+        );
+        self.state.notify.notify_waiters();
+        outcome
+    }
+
+    /// Waits until the sequence reaches or passes `target`.
+    pub async fn wait_for_phase(&self, target: CoordinatedPhase) {
+        let state = Arc::clone(&self.state);
+        let reached = || CoordinatedPhase::from_u8(state.phase.load(Ordering::Acquire)) >= target;
+        loop {
+            if reached() {
+                return;
+            }
+            let mut notified = std::pin::pin!(state.notify.notified());
+            std::future::poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready() || reached() {
+                    return std::task::Poll::Ready(());
+                }
+                std::task::Poll::Pending
+            })
+            .await;
+        }
+    }
+
+    /// Drives the sequence to [`Stopped`](CoordinatedPhase::Stopped).
+    ///
+    /// Waits for [`request_shutdown`](Self::request_shutdown), holds the
+    /// propagation delay, stops accepts, and waits for the server to report
+    /// stopped. Returns the final metrics.
+    pub async fn run(&self) -> CoordinatedShutdownMetrics {
+        self.drive(None).await
+    }
+
+    /// Like [`run`](Self::run), but also follows `controller`.
+    ///
+    /// The controller's first shutdown request (typically the first SIGTERM)
+    /// starts the sequence; its next request skips the propagation delay.
+    pub async fn run_with_controller(
+        &self,
+        controller: &ShutdownController,
+    ) -> CoordinatedShutdownMetrics {
+        self.drive(Some(controller.subscribe())).await
+    }
+
+    /// Stops `app` once the sequence is [`Stopped`](CoordinatedPhase::Stopped).
+    ///
+    /// Returns `None` without touching the app while the server is still
+    /// serving or draining.
+    pub fn stop_app(
+        &self,
+        app: &mut AppHandle,
+        state: &mut RuntimeState,
+    ) -> Option<Result<StoppedApp, AppStopError>> {
+        (self.phase() == CoordinatedPhase::Stopped).then(|| app.stop(state))
+    }
+
+    async fn drive(&self, mut requests: Option<ShutdownReceiver>) -> CoordinatedShutdownMetrics {
+        // Controller requests already folded into the sequence.
+        let mut forwarded = 0;
+        if let Some(receiver) = requests.as_mut() {
+            race(self.wait_for_phase(CoordinatedPhase::Draining), receiver.wait()).await;
+            if receiver.is_shutting_down() {
+                forwarded = 1;
+                self.request_shutdown();
+            }
+        } else {
+            self.wait_for_phase(CoordinatedPhase::Draining).await;
+        }
+
+        if let Some(deadline) = self.accept_stop_deadline() {
+            let escalation = async {
+                match requests.as_mut() {
+                    Some(receiver) => {
+                        receiver.wait_for_requests(forwarded + 1).await;
+                        self.request_shutdown();
+                    }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            race(
+                self.state.signal.wait_until(deadline),
+                race(self.wait_expedited(), escalation),
+            )
+            .await;
+            let cause = if self.state.expedited.load(Ordering::Acquire) {
+                TransitionCause::PropagationSkipped
+            } else {
+                TransitionCause::PropagationElapsed
+            };
+            self.transition(CoordinatedPhase::Draining, CoordinatedPhase::Stopping, cause);
+        }
+
+        self.state
+            .signal
+            .wait_for_phase(ShutdownPhase::Stopped)
+            .await;
+        self.transition(
+            CoordinatedPhase::Stopping,
+            CoordinatedPhase::Stopped,
+            TransitionCause::ServerStopped,
+        );
+        self.metrics()
+    }
+
+    async fn wait_expedited(&self) {
+        let state = Arc::clone(&self.state);
+        loop {
+            if state.expedited.load(Ordering::Acquire) {
+                return;
+            }
+            let mut notified = std::pin::pin!(state.notify.notified());
+            std::future::poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready()
+                    || state.expedited.load(Ordering::Acquire)
+                {
+                    return std::task::Poll::Ready(());
+                }
+                std::task::Poll::Pending
+            })
+            .await;
+        }
+    }
+
+    /// Moves from `from` to `to`, applying the side effects of entering `to`.
+    ///
+    /// Runs under the timeline lock so observers never see the new phase
+    /// before its side effects (readiness flip, accept stop) are in place.
+    fn transition(
+        &self,
+        from: CoordinatedPhase,
+        to: CoordinatedPhase,
+        cause: TransitionCause,
+    ) -> bool {
+        let mut timeline = self.state.timeline.lock();
+        if self.phase() != from {
+            return false;
+        }
+        let at = self.state.signal.current_time();
+        match to {
+            CoordinatedPhase::Draining => {
+                let delay = self.state.config.propagation_delay;
+                let deadline =
+                    at.saturating_add_nanos(delay.as_nanos().min(u128::from(u64::MAX)) as u64);
+                let signal = self.state.signal.clone();
+                self.state.health.begin_draining(DrainNotice::new(
+                    self.state.config.retry_after,
+                    deadline,
+                    move || signal.current_time(),
+                ));
+                timeline.accept_stop_deadline = Some(deadline);
+            }
+            CoordinatedPhase::Stopping => {
+                // A server that is already shutting down keeps its own drain.
+                let _ = self.state.signal.begin_drain(self.state.config.drain_timeout);
+            }
+            CoordinatedPhase::Serving | CoordinatedPhase::Stopped => {}
+        }
+        self.state.phase.store(to as u8, Ordering::Release);
+        timeline.events.push(CoordinatedShutdownEvent {
+            from,
+            to,
+            at,
+            cause,
+        });
+        drop(timeline);
+        info!(
+            from = %from,
+            to = %to,
+            at_nanos = at.as_nanos(),
+            cause = ?cause,
+            "coordinated shutdown transition"
+        );
+        self.state.notify.notify_waiters();
+        true
+    }
+}
+
+impl std::fmt::Debug for CoordinatedShutdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoordinatedShutdown")
+            .field("phase", &self.phase())
+            .field("config", &self.state.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Completes when either future completes.
+async fn race(a: impl Future<Output = ()>, b: impl Future<Output = ()>) {
+    let mut a = std::pin::pin!(a);
+    let mut b = std::pin::pin!(b);
+    std::future::poll_fn(|cx| {
+        if a.as_mut().poll(cx).is_ready() || b.as_mut().poll(cx).is_ready() {
+            return std::task::Poll::Ready(());
+        }
+        std::task::Poll::Pending
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::http::h1::Http1Config;
+    use crate::http::h1::listener::{Http1Listener, Http1ListenerConfig};
+    use crate::http::h1::server::HostPolicy;
+    use crate::http::h1::types::Response;
+    use crate::io::{AsyncReadExt, AsyncWriteExt};
+    use crate::net::tcp::stream::TcpStream;
+    use crate::runtime::{RuntimeBuilder, yield_now};
+    use crate::test_utils::init_test_logging;
+    use crate::web::extract::Request;
+    use crate::web::handler::Handler;
+    use crate::web::response::StatusCode;
+    use std::task::{Context, Poll, Waker};
+
+    const PROPAGATION: Duration = Duration::from_secs(5);
+    const DRAIN: Duration = Duration::from_secs(30);
+
+    fn init_test(name: &str) {
+        init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn config() -> CoordinatedShutdownConfig {
+        CoordinatedShutdownConfig::default()
+            .propagation_delay(PROPAGATION)
+            .retry_after(Duration::from_secs(2))
+            .drain_timeout(DRAIN)
+    }
+
+    fn poll_once<F: Future + Unpin>(fut: &mut F) -> Poll<F::Output> {
+        let mut cx = Context::from_waker(Waker::noop());
+        std::pin::Pin::new(fut).poll(&mut cx)
+    }
+
+    fn event(
+        from: CoordinatedPhase,
+        to: CoordinatedPhase,
+        at: Time,
+        cause: TransitionCause,
+    ) -> CoordinatedShutdownEvent {
+        CoordinatedShutdownEvent {
+            from,
+            to,
+            at,
+            cause,
+        }
+    }
+
+    #[test]
+    fn readiness_flips_exactly_at_sequence_start() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        fn now() -> Time {
+            Time::from_nanos(NOW.load(Ordering::SeqCst))
+        }
+
+        init_test("readiness_flips_exactly_at_sequence_start");
+        NOW.store(Time::from_secs(3).as_nanos(), Ordering::SeqCst);
+        let health = HealthCheck::new();
+        let signal = ShutdownSignal::with_time_getter(now);
+        let coordinator = CoordinatedShutdown::new(config(), signal.clone(), health.clone());
+
+        let ready = health.is_ready();
+        crate::assert_with_log!(ready, "ready while serving", true, ready);
+
+        let outcome = coordinator.request_shutdown();
+        crate::assert_with_log!(
+            outcome == ShutdownRequestOutcome::Started,
+            "first request starts",
+            ShutdownRequestOutcome::Started,
+            outcome
+        );
+        let draining = health.is_draining();
+        crate::assert_with_log!(draining, "readiness flipped", true, draining);
+        let events = coordinator.events();
+        let expected = vec![event(
+            CoordinatedPhase::Serving,
+            CoordinatedPhase::Draining,
+            Time::from_secs(3),
+            TransitionCause::ShutdownRequested,
+        )];
+        crate::assert_with_log!(events == expected, "start event", expected, events);
+        let phase = signal.phase();
+        crate::assert_with_log!(
+            phase == ShutdownPhase::Running,
+            "still accepting while draining readiness",
+            ShutdownPhase::Running,
+            phase
+        );
+
+        NOW.store(Time::from_secs(4).as_nanos(), Ordering::SeqCst);
+        let probe = futures_lite::future::block_on(Handler::call(
+            &health.readiness_handler(),
+            &crate::Cx::for_testing(),
+            Request::new("GET", "/readyz"),
+        ));
+        crate::assert_with_log!(
+            probe.status == StatusCode::SERVICE_UNAVAILABLE,
+            "readyz unavailable",
+            StatusCode::SERVICE_UNAVAILABLE,
+            probe.status
+        );
+        let retry_after = probe.header_value("retry-after");
+        crate::assert_with_log!(
+            retry_after == Some("2"),
+            "retry-after",
+            Some("2"),
+            retry_after
+        );
+        let deadline = probe.header_value("drain-deadline");
+        crate::assert_with_log!(
+            deadline == Some("4000"),
+            "drain-deadline counts down to accept stop",
+            Some("4000"),
+            deadline
+        );
+        crate::test_complete!("readiness_flips_exactly_at_sequence_start");
+    }
+
+    #[test]
+    fn full_timeline_matches_configuration() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        fn now() -> Time {
+            Time::from_nanos(NOW.load(Ordering::SeqCst))
+        }
+
+        init_test("full_timeline_matches_configuration");
+        let start = Time::from_secs(10);
+        NOW.store(start.as_nanos(), Ordering::SeqCst);
+        let signal = ShutdownSignal::with_time_getter(now);
+        let coordinator = CoordinatedShutdown::new(config(), signal.clone(), HealthCheck::new());
+        let mut run = Box::pin(coordinator.run());
+
+        let pending = poll_once(&mut run).is_pending();
+        crate::assert_with_log!(pending, "idle until requested", true, pending);
+        coordinator.request_shutdown();
+        let pending = poll_once(&mut run).is_pending();
+        crate::assert_with_log!(pending, "holds propagation delay", true, pending);
+
+        let accept_stop = start.saturating_add_nanos(PROPAGATION.as_nanos() as u64);
+        NOW.store(accept_stop.as_nanos() - 1, Ordering::SeqCst);
+        let _ = poll_once(&mut run);
+        let phase = coordinator.phase();
+        crate::assert_with_log!(
+            phase == CoordinatedPhase::Draining,
+            "accepts continue until the deadline",
+            CoordinatedPhase::Draining,
+            phase
+        );
+
+        NOW.store(accept_stop.as_nanos(), Ordering::SeqCst);
+        let pending = poll_once(&mut run).is_pending();
+        crate::assert_with_log!(pending, "waits for server stop", true, pending);
+        let phase = coordinator.phase();
+        crate::assert_with_log!(
+            phase == CoordinatedPhase::Stopping,
+            "stopping at the deadline",
+            CoordinatedPhase::Stopping,
+            phase
+        );
+        let server_phase = signal.phase();
+        crate::assert_with_log!(
+            server_phase == ShutdownPhase::Draining,
+            "server drains connections",
+            ShutdownPhase::Draining,
+            server_phase
+        );
+        let drain_deadline = signal.drain_deadline();
+        let expected = Some(accept_stop.saturating_add_nanos(DRAIN.as_nanos() as u64));
+        crate::assert_with_log!(
+            drain_deadline == expected,
+            "drain timeout from config",
+            expected,
+            drain_deadline
+        );
+
+        let stopped_at = accept_stop.saturating_add_nanos(1_500_000_000);
+        NOW.store(stopped_at.as_nanos(), Ordering::SeqCst);
+        signal.mark_stopped();
+        let metrics = match poll_once(&mut run) {
+            Poll::Ready(metrics) => metrics,
+            Poll::Pending => panic!("run must finish once the server stops"),
+        };
+
+        let expected = vec![
+            event(
+                CoordinatedPhase::Serving,
+                CoordinatedPhase::Draining,
+                start,
+                TransitionCause::ShutdownRequested,
+            ),
+            event(
+                CoordinatedPhase::Draining,
+                CoordinatedPhase::Stopping,
+                accept_stop,
+                TransitionCause::PropagationElapsed,
+            ),
+            event(
+                CoordinatedPhase::Stopping,
+                CoordinatedPhase::Stopped,
+                stopped_at,
+                TransitionCause::ServerStopped,
+            ),
+        ];
+        let events = coordinator.events();
+        crate::assert_with_log!(events == expected, "timeline", expected, events);
+        let expected = CoordinatedShutdownMetrics {
+            phase: CoordinatedPhase::Stopped,
+            shutdown_requests: 1,
+            transitions: 3,
+            propagation_skipped: false,
+            readiness_flipped_at: Some(start),
+            accept_stopped_at: Some(accept_stop),
+            stopped_at: Some(stopped_at),
+        };
+        crate::assert_with_log!(metrics == expected, "metrics", expected, metrics);
+        crate::test_complete!("full_timeline_matches_configuration");
+    }
+
+    #[test]
+    fn second_sigterm_skips_propagation_delay() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        fn now() -> Time {
+            Time::from_nanos(NOW.load(Ordering::SeqCst))
+        }
+
+        init_test("second_sigterm_skips_propagation_delay");
+        NOW.store(Time::from_secs(1).as_nanos(), Ordering::SeqCst);
+        let signal = ShutdownSignal::with_time_getter(now);
+        let coordinator = CoordinatedShutdown::new(config(), signal.clone(), HealthCheck::new());
+        let controller = ShutdownController::new();
+        let mut run = Box::pin(coordinator.run_with_controller(&controller));
+        let _ = poll_once(&mut run);
+
+        // The signal listener calls `shutdown()` once per delivered SIGTERM.
+        controller.shutdown();
+        let _ = poll_once(&mut run);
+        let phase = coordinator.phase();
+        crate::assert_with_log!(
+            phase == CoordinatedPhase::Draining,
+            "first SIGTERM starts the sequence",
+            CoordinatedPhase::Draining,
+            phase
+        );
+
+        NOW.store(Time::from_secs(2).as_nanos(), Ordering::SeqCst);
+        controller.shutdown();
+        let _ = poll_once(&mut run);
+        let phase = coordinator.phase();
+        crate::assert_with_log!(
+            phase == CoordinatedPhase::Stopping,
+            "second SIGTERM stops accepts immediately",
+            CoordinatedPhase::Stopping,
+            phase
+        );
+        let accepts_stopped = signal.is_draining();
+        crate::assert_with_log!(accepts_stopped, "server draining", true, accepts_stopped);
+
+        signal.mark_stopped();
+        let metrics = match poll_once(&mut run) {
+            Poll::Ready(metrics) => metrics,
+            Poll::Pending => panic!("run must finish once the server stops"),
+        };
+        crate::assert_with_log!(
+            metrics.propagation_skipped,
+            "propagation skipped",
+            true,
+            metrics.propagation_skipped
+        );
+        let accept_stopped_at = metrics.accept_stopped_at;
+        crate::assert_with_log!(
+            accept_stopped_at == Some(Time::from_secs(2)),
+            "accept stop at the second SIGTERM",
+            Some(Time::from_secs(2)),
+            accept_stopped_at
+        );
+        let requests = metrics.shutdown_requests;
+        crate::assert_with_log!(requests == 2, "requests", 2, requests);
+        crate::test_complete!("second_sigterm_skips_propagation_delay");
+    }
+
+    #[test]
+    fn serves_during_propagation_and_refuses_after_accept_stop() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+        fn now() -> Time {
+            Time::from_nanos(NOW.load(Ordering::SeqCst))
+        }
+
+        init_test("serves_during_propagation_and_refuses_after_accept_stop");
+        let runtime = RuntimeBuilder::current_thread()
+            .build()
+            .expect("build runtime");
+        let handle = runtime.handle();
+
+        runtime.block_on(async {
+            let release = Arc::new(Notify::new());
+            let release_handler = Arc::clone(&release);
+            let listener_config = Http1ListenerConfig {
+                http_config: Http1Config {
+                    allowed_hosts: HostPolicy::allow_list(vec!["localhost".to_owned()]),
+                    ..Http1Config::default()
+                },
+                time_getter: now,
+                ..Default::default()
+            };
+            let listener = Http1Listener::bind_with_config(
+                "127.0.0.1:0",
+                move |req: crate::http::h1::types::Request| {
+                    let release = Arc::clone(&release_handler);
+                    async move {
+                        if req.uri == "/slow" {
+                            release.notified().await;
+                        }
+                        Response::new(200, "OK", b"served".to_vec())
+                    }
+                },
+                listener_config,
+            )
+            .await
+            .expect("bind failed");
+            let addr = listener.local_addr().expect("local_addr");
+            let coordinator =
+                CoordinatedShutdown::new(config(), listener.shutdown_signal(), HealthCheck::new());
+
+            let listener_handle = handle.clone();
+            let run_listener = handle
+                .clone()
+                .try_spawn(async move { listener.run(&listener_handle).await })
+                .expect("spawn listener");
+            let driver = coordinator.clone();
+            let run_coordinator = handle
+                .clone()
+                .try_spawn(async move { driver.run().await })
+                .expect("spawn coordinator");
+
+            coordinator.request_shutdown();
+
+            // A connection opened during the propagation window is served.
+            let mut client = TcpStream::connect(addr).await.expect("connect in window");
+            client
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .expect("write request");
+            let mut response = Vec::new();
+            client
+                .read_to_end(&mut response)
+                .await
+                .expect("read response");
+            let head = String::from_utf8_lossy(&response);
+            assert!(head.starts_with("HTTP/1.1 200"), "served in window: {head}");
+
+            // Keep one request in flight across the accept stop.
+            let mut slow = TcpStream::connect(addr).await.expect("connect slow");
+            slow.write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .expect("write slow request");
+            for _ in 0..8 {
+                yield_now().await;
+            }
+
+            NOW.store(PROPAGATION.as_nanos() as u64, Ordering::SeqCst);
+            coordinator
+                .wait_for_phase(CoordinatedPhase::Stopping)
+                .await;
+
+            let mut refused = false;
+            for _ in 0..100 {
+                match TcpStream::connect(addr).await {
+                    Err(err) if err.kind() == std::io::ErrorKind::ConnectionRefused => {
+                        refused = true;
+                        break;
+                    }
+                    _ => yield_now().await,
+                }
+            }
+            assert!(refused, "new connections are refused after accept stop");
+            assert_eq!(coordinator.phase(), CoordinatedPhase::Stopping);
+
+            release.notify_one();
+            let mut slow_response = Vec::new();
+            slow.read_to_end(&mut slow_response)
+                .await
+                .expect("read slow response");
+            let head = String::from_utf8_lossy(&slow_response);
+            assert!(head.starts_with("HTTP/1.1 200"), "in-flight drained: {head}");
+
+            run_listener.await.expect("listener run");
+            let metrics = run_coordinator.await;
+            assert_eq!(metrics.phase, CoordinatedPhase::Stopped);
+            assert_eq!(
+                metrics.accept_stopped_at,
+                Some(Time::from_nanos(PROPAGATION.as_nanos() as u64))
+            );
+        });
+        crate::test_complete!("serves_during_propagation_and_refuses_after_accept_stop");
+    }
+}
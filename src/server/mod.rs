@@ -6,6 +6,8 @@
 //! - [`ShutdownPhase`] — Shutdown state machine (Running → Draining → ForceClosing → Stopped)
 //! - [`ConnectionManager`] — Active connection tracking with capacity limits
 //! - [`ConnectionGuard`] — RAII guard for automatic connection deregistration
+//! - [`CoordinatedShutdown`] — LB-aware shutdown (Serving → Draining → Stopping → Stopped)
+//!   that flips readiness before accepts stop
 //!
 //! These types build on the lower-level [`ShutdownController`](crate::signal::ShutdownController)
 //! to provide server-specific lifecycle management with structured concurrency semantics.
@@ -50,9 +52,14 @@
 //! ```
 
 pub mod connection;
+pub mod coordinated;
 pub mod shutdown;
 
 pub use connection::{ConnectionGuard, ConnectionId, ConnectionInfo, ConnectionManager};
+pub use coordinated::{
+    CoordinatedPhase, CoordinatedShutdown, CoordinatedShutdownConfig, CoordinatedShutdownEvent,
+    CoordinatedShutdownMetrics, ShutdownRequestOutcome, TransitionCause,
+};
 pub use shutdown::{
     DrainStep, GracefulDrainReport, GracefulDrainSupervisor, GracefulDrainTracker, ShutdownPhase,
     ShutdownSignal, ShutdownStats,
@@ -0,0 +1,610 @@
+//! Adaptive concurrency limit middleware layer.
+//!
+//! The [`AdaptiveConcurrencyLayer`] wraps an outbound service with a shared
+//! [`AdaptiveLimiter`]. Calls beyond the current limit fail immediately with
+//! [`AdaptiveConcurrencyError::Rejected`]; admitted calls feed their latency
+//! and classified outcome back into the limit algorithm when the inner
+//! future resolves. Dropping the response future (e.g. on cancellation)
+//! releases the permit without a sample.
+//!
+//! Outcome classification reuses the circuit breaker's [`ResultClassifier`]:
+//! [`Disposition::Failure`] shrinks the limit like a timeout would, and
+//! [`Disposition::Ignore`] releases the slot without adjusting it.
+
+use super::circuit_breaker::{DefaultClassifier, Disposition, ResultClassifier};
+use super::{Layer, Service};
+use crate::combinator::adaptive_concurrency::{
+    AdaptiveLimitConfig, AdaptiveLimitMetrics, AdaptiveLimiter, AdaptivePermit, LimitAlgorithm,
+    LimitOutcome, LimitRejected,
+};
+use crate::types::Time;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+fn wall_clock_now() -> Time {
+    crate::time::wall_now()
+}
+
+/// A layer that applies an adaptive concurrency limit to requests.
+///
+/// All services produced by one layer share a single limiter, so the limit
+/// reflects the combined load on the downstream.
+#[derive(Debug, Clone)]
+pub struct AdaptiveConcurrencyLayer<C = DefaultClassifier> {
+    limiter: Arc<AdaptiveLimiter>,
+    time_getter: fn() -> Time,
+    classifier: C,
+}
+
+impl AdaptiveConcurrencyLayer {
+    /// Creates a layer with a fresh limiter driven by `algorithm`.
+    #[must_use]
+    pub fn new(config: AdaptiveLimitConfig, algorithm: impl LimitAlgorithm + 'static) -> Self {
+        Self::from_shared(Arc::new(AdaptiveLimiter::new(config, algorithm)))
+    }
+
+    /// Creates a layer sharing an existing limiter.
+    #[must_use]
+    pub fn from_shared(limiter: Arc<AdaptiveLimiter>) -> Self {
+        Self {
+            limiter,
+            time_getter: wall_clock_now,
+            classifier: DefaultClassifier,
+        }
+    }
+}
+
+impl<C> AdaptiveConcurrencyLayer<C> {
+    /// Replaces the outcome classifier.
+    #[must_use]
+    pub fn with_classifier<C2>(self, classifier: C2) -> AdaptiveConcurrencyLayer<C2> {
+        AdaptiveConcurrencyLayer {
+            limiter: self.limiter,
+            time_getter: self.time_getter,
+            classifier,
+        }
+    }
+
+    /// Replaces the time source used to measure call latency.
+    #[must_use]
+    pub fn with_time_getter(mut self, time_getter: fn() -> Time) -> Self {
+        self.time_getter = time_getter;
+        self
+    }
+
+    /// Returns the shared limiter.
+    #[must_use]
+    pub fn limiter(&self) -> &Arc<AdaptiveLimiter> {
+        &self.limiter
+    }
+
+    /// Returns the time source used by this layer.
+    #[must_use]
+    pub const fn time_getter(&self) -> fn() -> Time {
+        self.time_getter
+    }
+}
+
+impl<S, C: Clone> Layer<S> for AdaptiveConcurrencyLayer<C> {
+    type Service = AdaptiveConcurrency<S, C>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AdaptiveConcurrency {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+            time_getter: self.time_getter,
+            ready_observed: false,
+            classifier: self.classifier.clone(),
+        }
+    }
+}
+
+/// Service wrapper that enforces an adaptive concurrency limit.
+#[derive(Debug)]
+pub struct AdaptiveConcurrency<S, C = DefaultClassifier> {
+    inner: S,
+    limiter: Arc<AdaptiveLimiter>,
+    time_getter: fn() -> Time,
+    ready_observed: bool,
+    classifier: C,
+}
+
+impl<S: Clone, C: Clone> Clone for AdaptiveConcurrency<S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limiter: Arc::clone(&self.limiter),
+            time_getter: self.time_getter,
+            ready_observed: false,
+            classifier: self.classifier.clone(),
+        }
+    }
+}
+
+impl<S> AdaptiveConcurrency<S> {
+    /// Creates a service with a fresh limiter driven by `algorithm`.
+    #[must_use]
+    pub fn new(
+        inner: S,
+        config: AdaptiveLimitConfig,
+        algorithm: impl LimitAlgorithm + 'static,
+    ) -> Self {
+        AdaptiveConcurrencyLayer::new(config, algorithm).layer(inner)
+    }
+}
+
+impl<S, C> AdaptiveConcurrency<S, C> {
+    /// Returns the shared limiter.
+    #[must_use]
+    pub fn limiter(&self) -> &Arc<AdaptiveLimiter> {
+        &self.limiter
+    }
+
+    /// Returns the current concurrency limit.
+    #[must_use]
+    pub fn limit(&self) -> u32 {
+        self.limiter.limit()
+    }
+
+    /// Returns current limiter metrics.
+    #[must_use]
+    pub fn metrics(&self) -> AdaptiveLimitMetrics {
+        self.limiter.metrics()
+    }
+
+    /// Returns a reference to the inner service.
+    #[must_use]
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner service.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consumes the wrapper, returning the inner service.
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+/// Error returned by the adaptive concurrency middleware.
+#[derive(Debug)]
+pub enum AdaptiveConcurrencyError<E> {
+    /// The caller attempted `call()` without a preceding successful `poll_ready()`.
+    NotReady,
+    /// The future was polled after it had already completed.
+    PolledAfterCompletion,
+    /// The concurrency limit was reached; the call never reached the inner
+    /// service.
+    Rejected(LimitRejected),
+    /// The inner service returned an error.
+    Inner(E),
+}
+
+impl<E> AdaptiveConcurrencyError<E> {
+    /// Returns true when the limiter rejected the call before it reached the
+    /// inner service. Retry policies should back off rather than retry
+    /// immediately, since the downstream is signalling overload.
+    #[must_use]
+    pub const fn is_rejected(&self) -> bool {
+        matches!(self, Self::Rejected(_))
+    }
+
+    /// Returns true when the inner service returned the error.
+    #[must_use]
+    pub const fn is_inner(&self) -> bool {
+        matches!(self, Self::Inner(_))
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for AdaptiveConcurrencyError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotReady => write!(f, "poll_ready required before call"),
+            Self::PolledAfterCompletion => {
+                write!(f, "adaptive concurrency future polled after completion")
+            }
+            Self::Rejected(rejected) => write!(f, "{rejected}"),
+            Self::Inner(e) => write!(f, "inner service error: {e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for AdaptiveConcurrencyError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Rejected(rejected) => Some(rejected),
+            Self::Inner(e) => Some(e),
+            Self::NotReady | Self::PolledAfterCompletion => None,
+        }
+    }
+}
+
+impl<S, C, Request> Service<Request> for AdaptiveConcurrency<S, C>
+where
+    S: Service<Request>,
+    S::Future: Unpin,
+    C: ResultClassifier<S::Response, S::Error> + Clone + Unpin,
+{
+    type Response = S::Response;
+    type Error = AdaptiveConcurrencyError<S::Error>;
+    type Future = AdaptiveConcurrencyFuture<S::Future, C>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.inner.poll_ready(cx) {
+            Poll::Ready(Ok(())) => {
+                self.ready_observed = true;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(err)) => {
+                self.ready_observed = false;
+                Poll::Ready(Err(AdaptiveConcurrencyError::Inner(err)))
+            }
+            Poll::Pending => {
+                self.ready_observed = false;
+                Poll::Pending
+            }
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if !std::mem::replace(&mut self.ready_observed, false) {
+            return AdaptiveConcurrencyFuture::not_ready();
+        }
+
+        match self.limiter.try_acquire((self.time_getter)()) {
+            Ok(permit) => AdaptiveConcurrencyFuture {
+                state: AdaptiveConcurrencyFutureState::Running {
+                    inner: self.inner.call(req),
+                    permit,
+                    time_getter: self.time_getter,
+                    classifier: self.classifier.clone(),
+                },
+            },
+            Err(rejected) => AdaptiveConcurrencyFuture::rejected(rejected),
+        }
+    }
+}
+
+/// Future returned by [`AdaptiveConcurrency`] service.
+#[derive(Debug)]
+pub struct AdaptiveConcurrencyFuture<F, C = DefaultClassifier> {
+    state: AdaptiveConcurrencyFutureState<F, C>,
+}
+
+#[derive(Debug)]
+enum AdaptiveConcurrencyFutureState<F, C> {
+    NotReady,
+    Rejected(LimitRejected),
+    Running {
+        inner: F,
+        permit: AdaptivePermit,
+        time_getter: fn() -> Time,
+        classifier: C,
+    },
+    Done,
+}
+
+impl<F, C> AdaptiveConcurrencyFuture<F, C> {
+    /// Creates a future that immediately returns a readiness misuse error.
+    #[must_use]
+    pub const fn not_ready() -> Self {
+        Self {
+            state: AdaptiveConcurrencyFutureState::NotReady,
+        }
+    }
+
+    /// Creates a future that immediately returns a limit rejection.
+    #[must_use]
+    pub const fn rejected(rejected: LimitRejected) -> Self {
+        Self {
+            state: AdaptiveConcurrencyFutureState::Rejected(rejected),
+        }
+    }
+}
+
+impl<F, C, Response, Error> Future for AdaptiveConcurrencyFuture<F, C>
+where
+    F: Future<Output = Result<Response, Error>> + Unpin,
+    C: ResultClassifier<Response, Error> + Unpin,
+{
+    type Output = Result<Response, AdaptiveConcurrencyError<Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let state = std::mem::replace(&mut this.state, AdaptiveConcurrencyFutureState::Done);
+
+        match state {
+            AdaptiveConcurrencyFutureState::NotReady => {
+                Poll::Ready(Err(AdaptiveConcurrencyError::NotReady))
+            }
+            AdaptiveConcurrencyFutureState::Rejected(rejected) => {
+                Poll::Ready(Err(AdaptiveConcurrencyError::Rejected(rejected)))
+            }
+            AdaptiveConcurrencyFutureState::Running {
+                mut inner,
+                permit,
+                time_getter,
+                classifier,
+            } => match Pin::new(&mut inner).poll(cx) {
+                Poll::Pending => {
+                    this.state = AdaptiveConcurrencyFutureState::Running {
+                        inner,
+                        permit,
+                        time_getter,
+                        classifier,
+                    };
+                    Poll::Pending
+                }
+                Poll::Ready(output) => {
+                    let outcome = match classifier.classify(&output) {
+                        Disposition::Success => LimitOutcome::Success,
+                        Disposition::Failure => LimitOutcome::Failure,
+                        Disposition::Ignore => LimitOutcome::Ignore,
+                    };
+                    permit.record(outcome, time_getter());
+                    Poll::Ready(output.map_err(AdaptiveConcurrencyError::Inner))
+                }
+            },
+            AdaptiveConcurrencyFutureState::Done => {
+                Poll::Ready(Err(AdaptiveConcurrencyError::PolledAfterCompletion))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::combinator::adaptive_concurrency::Aimd;
+    use crate::service::retry::{Policy, Retry, RetryError};
+    use parking_lot::Mutex;
+    use std::cell::Cell;
+    use std::future::{Ready, ready};
+    use std::task::Waker;
+    use std::time::Duration;
+
+    std::thread_local! {
+        static TEST_NOW_MS: Cell<u64> = const { Cell::new(0) };
+    }
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+        set_test_time_ms(0);
+    }
+
+    fn test_time() -> Time {
+        Time::from_millis(TEST_NOW_MS.with(Cell::get))
+    }
+
+    fn set_test_time_ms(ms: u64) {
+        TEST_NOW_MS.with(|now| now.set(ms));
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let waker = Waker::noop().clone();
+        let mut cx = Context::from_waker(&waker);
+        Pin::new(future).poll(&mut cx)
+    }
+
+    fn ready_call<S: Service<()>>(service: &mut S) -> S::Future {
+        let waker = Waker::noop().clone();
+        let mut cx = Context::from_waker(&waker);
+        assert!(matches!(service.poll_ready(&mut cx), Poll::Ready(Ok(()))));
+        service.call(())
+    }
+
+    type Gate = Arc<Mutex<Option<Result<&'static str, &'static str>>>>;
+
+    /// Inner service whose futures stay pending until the gate is set.
+    #[derive(Clone, Debug)]
+    struct GatedService {
+        release: Gate,
+    }
+
+    #[derive(Debug)]
+    struct GatedFuture {
+        release: Gate,
+    }
+
+    impl Future for GatedFuture {
+        type Output = Result<&'static str, &'static str>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            (*self.release.lock()).map_or(Poll::Pending, Poll::Ready)
+        }
+    }
+
+    impl Service<()> for GatedService {
+        type Response = &'static str;
+        type Error = &'static str;
+        type Future = GatedFuture;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: ()) -> Self::Future {
+            GatedFuture {
+                release: Arc::clone(&self.release),
+            }
+        }
+    }
+
+    fn gated() -> (GatedService, Gate) {
+        let release = Arc::new(Mutex::new(None));
+        (
+            GatedService {
+                release: Arc::clone(&release),
+            },
+            release,
+        )
+    }
+
+    fn aimd_service(initial: u32) -> AdaptiveConcurrency<GatedService> {
+        let (inner, _) = gated();
+        AdaptiveConcurrencyLayer::new(
+            AdaptiveLimitConfig::default().initial_limit(initial),
+            Aimd::default(),
+        )
+        .with_time_getter(test_time)
+        .layer(inner)
+    }
+
+    #[test]
+    fn rejects_beyond_limit_with_typed_error() {
+        init_test("rejects_beyond_limit_with_typed_error");
+        let (inner, _release) = gated();
+        let mut service = AdaptiveConcurrencyLayer::new(
+            AdaptiveLimitConfig::default().initial_limit(2),
+            Aimd::default(),
+        )
+        .with_time_getter(test_time)
+        .layer(inner);
+
+        let mut first = ready_call(&mut service);
+        let mut second = ready_call(&mut service);
+        assert!(poll_once(&mut first).is_pending());
+        assert!(poll_once(&mut second).is_pending());
+
+        let mut third = ready_call(&mut service);
+        match poll_once(&mut third) {
+            Poll::Ready(Err(AdaptiveConcurrencyError::Rejected(rejected))) => {
+                assert_eq!(
+                    rejected,
+                    LimitRejected {
+                        limit: 2,
+                        in_flight: 2
+                    }
+                );
+            }
+            other => panic!("expected rejection, got {other:?}"),
+        }
+        assert_eq!(service.metrics().total_rejected, 1);
+        crate::test_complete!("rejects_beyond_limit_with_typed_error");
+    }
+
+    #[test]
+    fn completion_feeds_latency_and_outcome() {
+        init_test("completion_feeds_latency_and_outcome");
+        let (inner, release) = gated();
+        let mut service = AdaptiveConcurrencyLayer::new(
+            AdaptiveLimitConfig::default().initial_limit(2),
+            Aimd {
+                latency_target: Duration::from_millis(50),
+                ..Aimd::default()
+            },
+        )
+        .with_time_getter(test_time)
+        .layer(inner);
+
+        let mut fast = ready_call(&mut service);
+        assert!(poll_once(&mut fast).is_pending());
+        set_test_time_ms(10);
+        *release.lock() = Some(Ok("ok"));
+        assert!(matches!(poll_once(&mut fast), Poll::Ready(Ok("ok"))));
+        assert_eq!(service.limit(), 3);
+
+        *release.lock() = None;
+        let mut slow = ready_call(&mut service);
+        assert!(poll_once(&mut slow).is_pending());
+        set_test_time_ms(200);
+        *release.lock() = Some(Ok("ok"));
+        assert!(matches!(poll_once(&mut slow), Poll::Ready(Ok("ok"))));
+        assert_eq!(service.limit(), 2);
+
+        let adjustments = service.limiter().adjustments();
+        assert_eq!(adjustments.len(), 2);
+        assert_eq!(adjustments[1].at, Time::from_millis(200));
+        assert_eq!(adjustments[1].sample.latency, Duration::from_millis(190));
+        crate::test_complete!("completion_feeds_latency_and_outcome");
+    }
+
+    #[test]
+    fn dropped_futures_release_permits() {
+        init_test("dropped_futures_release_permits");
+        let mut service = aimd_service(3);
+        for _ in 0..5 {
+            let mut futures: Vec<_> = (0..3).map(|_| ready_call(&mut service)).collect();
+            for future in &mut futures {
+                assert!(poll_once(future).is_pending());
+            }
+            assert_eq!(service.limiter().in_flight(), 3);
+            drop(futures);
+            assert_eq!(service.limiter().in_flight(), 0);
+        }
+        let metrics = service.metrics();
+        assert_eq!(metrics.total_ignored, 15);
+        assert_eq!(metrics.limit, 3);
+        crate::test_complete!("dropped_futures_release_permits");
+    }
+
+    #[test]
+    fn call_without_poll_ready_is_rejected() {
+        init_test("call_without_poll_ready_is_rejected");
+        let mut service = aimd_service(1);
+        let mut future = service.call(());
+        assert!(matches!(
+            poll_once(&mut future),
+            Poll::Ready(Err(AdaptiveConcurrencyError::NotReady))
+        ));
+        assert_eq!(service.limiter().in_flight(), 0);
+        crate::test_complete!("call_without_poll_ready_is_rejected");
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct RetryInnerOnly {
+        remaining: usize,
+    }
+
+    impl Policy<(), &'static str, AdaptiveConcurrencyError<&'static str>> for RetryInnerOnly {
+        type Future = Ready<Self>;
+
+        fn retry(
+            &self,
+            _req: &(),
+            result: Result<&&'static str, &AdaptiveConcurrencyError<&'static str>>,
+        ) -> Option<Self::Future> {
+            match result {
+                Err(error) if error.is_inner() && self.remaining > 0 => Some(ready(Self {
+                    remaining: self.remaining - 1,
+                })),
+                Err(_) | Ok(_) => None,
+            }
+        }
+
+        fn clone_request(&self, _req: &()) -> Option<()> {
+            Some(())
+        }
+    }
+
+    #[test]
+    fn retry_policy_sees_rejection() {
+        init_test("retry_policy_sees_rejection");
+        let mut limited = aimd_service(1);
+        let mut held = ready_call(&mut limited);
+        assert!(poll_once(&mut held).is_pending());
+
+        let mut service = Retry::new(limited, RetryInnerOnly { remaining: 3 });
+        let mut future = ready_call(&mut service);
+        match poll_once(&mut future) {
+            Poll::Ready(Err(RetryError::Inner(error))) => assert!(error.is_rejected()),
+            other => panic!("expected rejection, got {other:?}"),
+        }
+        crate::test_complete!("retry_policy_sees_rejection");
+    }
+}
@@ -11,10 +11,12 @@
 //! - [`timeout`]: Impose time limits on requests
 //! - [`load_shed`]: Shed load when the inner service is not ready
 //! - [`concurrency_limit`]: Limit concurrent in-flight requests
+//! - [`adaptive_concurrency`]: Adapt the concurrency limit to downstream latency
 //! - [`rate_limit`]: Rate-limit requests using a token bucket
 //! - [`retry`]: Retry failed requests according to a policy
 //! - [`buffer`]: Buffer requests via a bounded channel
 
+pub mod adaptive_concurrency;
 pub mod buffer;
 mod builder;
 pub mod circuit_breaker;
@@ -32,6 +34,10 @@ mod service;
 pub mod steer;
 pub mod timeout;
 
+pub use adaptive_concurrency::{
+    AdaptiveConcurrency, AdaptiveConcurrencyError, AdaptiveConcurrencyFuture,
+    AdaptiveConcurrencyLayer,
+};
 pub use buffer::{Buffer, BufferError, BufferLayer};
 pub use builder::ServiceBuilder;
 pub use circuit_breaker::{
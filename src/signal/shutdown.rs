@@ -7,7 +7,7 @@
 use std::future::Future;
 use std::io;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use super::{SignalKind, signal};
 use crate::sync::Notify;
@@ -18,6 +18,8 @@ use crate::tracing_compat::{info, warn};
 struct ShutdownState {
     /// Tracks whether shutdown has been initiated.
     initiated: AtomicBool,
+    /// Number of shutdown requests (manual calls and signal deliveries).
+    requests: AtomicU64,
     /// Ensures signal listeners are only installed once per controller.
     signal_listeners_started: AtomicBool,
     /// Notifier for broadcast notifications.
@@ -409,6 +411,7 @@ impl ShutdownController {
         Self {
             state: Arc::new(ShutdownState {
                 initiated: AtomicBool::new(false),
+                requests: AtomicU64::new(0),
                 signal_listeners_started: AtomicBool::new(false),
                 notify: Notify::new(),
             }),
@@ -430,6 +433,8 @@ impl ShutdownController {
     ///
     /// This wakes all receivers that are currently waiting for shutdown.
     /// The shutdown state is persistent - once initiated, it cannot be reset.
+    /// Repeated calls are counted in [`request_count`](Self::request_count)
+    /// so callers can escalate on a second request.
     pub fn shutdown(&self) {
         Self::trigger_shutdown_state(&self.state);
     }
@@ -440,6 +445,15 @@ impl ShutdownController {
         self.state.initiated.load(Ordering::Acquire)
     }
 
+    /// Returns the number of shutdown requests recorded so far.
+    ///
+    /// Every [`shutdown`](Self::shutdown) call and every watched signal
+    /// delivery counts, so a second SIGTERM shows up as a count of 2.
+    #[must_use]
+    pub fn request_count(&self) -> u64 {
+        self.state.requests.load(Ordering::Acquire)
+    }
+
     /// Spawns a background task to listen for shutdown signals.
     ///
     /// This is a convenience method that sets up signal handling
@@ -449,7 +463,9 @@ impl ShutdownController {
     ///
     /// The listeners are installed at most once per controller. When a watched
     /// signal arrives, the controller transitions to shutdown just as if
-    /// [`ShutdownController::shutdown`] had been called manually.
+    /// [`ShutdownController::shutdown`] had been called manually. Listeners
+    /// keep running afterwards so repeated signals are reflected in
+    /// [`request_count`](Self::request_count).
     pub fn listen_for_signals(self: &Arc<Self>) {
         if self
             .state
@@ -476,13 +492,18 @@ impl ShutdownController {
     }
 
     fn trigger_shutdown_state(state: &ShutdownState) {
+        state.requests.fetch_add(1, Ordering::AcqRel);
         if state
             .initiated
             .compare_exchange(false, true, Ordering::Release, Ordering::Relaxed)
-            .is_ok()
+            .is_err()
         {
-            state.notify.notify_waiters();
+            info!(
+                shutdown_requests = state.requests.load(Ordering::Acquire),
+                "repeated shutdown request"
+            );
         }
+        state.notify.notify_waiters();
     }
 
     fn spawn_signal_listener(
@@ -496,9 +517,10 @@ impl ShutdownController {
                 kind.name().to_ascii_lowercase()
             ))
             .spawn(move || {
-                if futures_lite::future::block_on(stream.recv()).is_some()
-                    && let Some(state) = state.upgrade()
-                {
+                while futures_lite::future::block_on(stream.recv()).is_some() {
+                    let Some(state) = state.upgrade() else {
+                        break;
+                    };
                     Self::trigger_shutdown_state(&state);
                 }
             })
@@ -583,6 +605,37 @@ impl ShutdownReceiver {
     pub fn is_shutting_down(&self) -> bool {
         self.state.initiated.load(Ordering::Acquire)
     }
+
+    /// Returns the number of shutdown requests recorded by the controller.
+    #[must_use]
+    pub fn request_count(&self) -> u64 {
+        self.state.requests.load(Ordering::Acquire)
+    }
+
+    /// Waits until at least `count` shutdown requests have been recorded.
+    ///
+    /// `wait_for_requests(2)` resolves on the second SIGTERM (or second
+    /// manual [`ShutdownController::shutdown`] call), which callers use to
+    /// escalate a graceful shutdown that is already underway.
+    pub async fn wait_for_requests(&mut self, count: u64) {
+        let state = Arc::clone(&self.state);
+        loop {
+            if state.requests.load(Ordering::Acquire) >= count {
+                return;
+            }
+
+            let mut notified = std::pin::pin!(state.notify.notified());
+            std::future::poll_fn(|cx| {
+                if std::future::Future::poll(notified.as_mut(), cx).is_ready()
+                    || state.requests.load(Ordering::Acquire) >= count
+                {
+                    return std::task::Poll::Ready(());
+                }
+                std::task::Poll::Pending
+            })
+            .await;
+        }
+    }
 }
 
 impl Clone for ShutdownReceiver {
@@ -699,6 +752,28 @@ mod tests {
         crate::test_complete!("shutdown_only_once");
     }
 
+    #[test]
+    fn repeated_requests_are_counted_and_wake_escalation_waiters() {
+        init_test("repeated_requests_are_counted_and_wake_escalation_waiters");
+        let controller = ShutdownController::new();
+        let mut receiver = controller.subscribe();
+
+        let mut second = Box::pin(receiver.wait_for_requests(2));
+        let pending = poll_once(&mut second).is_pending();
+        crate::assert_with_log!(pending, "pending before any request", true, pending);
+
+        controller.shutdown();
+        let pending = poll_once(&mut second).is_pending();
+        crate::assert_with_log!(pending, "first request does not escalate", true, pending);
+
+        controller.shutdown();
+        let ready = poll_once(&mut second).is_ready();
+        crate::assert_with_log!(ready, "second request escalates", true, ready);
+        let count = controller.request_count();
+        crate::assert_with_log!(count == 2, "request count", 2, count);
+        crate::test_complete!("repeated_requests_are_counted_and_wake_escalation_waiters");
+    }
+
     #[test]
     fn multiple_receivers() {
         init_test("multiple_receivers");
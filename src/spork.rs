@@ -138,9 +138,9 @@ pub mod supervisor {
 /// - `call` replies are linear obligations (reply or abort).
 pub mod gen_server {
     pub use crate::gen_server::{
-        CallError, CastError, CastOverflowPolicy, DownMsg, ExitMsg, GenServer, GenServerHandle,
-        GenServerRef, InfoError, NamedGenServerStart, Reply, ReplyOutcome, SystemMsg, TimeoutMsg,
-        named_gen_server_start,
+        CallError, CastAdmission, CastError, CastOverflowPolicy, DownMsg, ExitMsg, GenServer,
+        GenServerHandle, GenServerRef, InfoError, MailboxMetrics, MailboxSpec, NamedGenServerStart,
+        Reply, ReplyOutcome, SystemMsg, TimeoutMsg, named_gen_server_start,
     };
 }
 
@@ -1632,7 +1632,7 @@ pub mod monitor {
 /// Shutdown ordering contract: link exits follow `Down` and precede timeouts
 /// for equal virtual timestamps.
 pub mod link {
-    pub use crate::link::{ExitPolicy, ExitSignal, LinkRef};
+    pub use crate::link::{ExitMailbox, ExitPolicy, ExitSignal, LinkRef};
 }
 
 /// Crash pack format and artifact writing.
@@ -1805,14 +1805,17 @@ pub mod error {
 
         fn runtime_spawn_severity(error: &SpawnError) -> SporkSeverity {
             match error {
-                SpawnError::RegionAtCapacity { .. } => SporkSeverity::Transient,
+                SpawnError::RegionAtCapacity { .. } | SpawnError::AtCapacity { .. } => {
+                    SporkSeverity::Transient
+                }
                 SpawnError::RuntimeUnavailable
                 | SpawnError::RegionNotFound(_)
                 | SpawnError::RegionClosed(_)
                 | SpawnError::LocalSchedulerUnavailable
                 | SpawnError::NameRegistrationFailed { .. }
                 | SpawnError::AuthorizationDenied { .. }
-                | SpawnError::AdmissionSlotAlreadyReserved { .. } => SporkSeverity::Permanent,
+                | SpawnError::AdmissionSlotAlreadyReserved { .. }
+                | SpawnError::CapabilityDenied(_) => SporkSeverity::Permanent,
             }
         }
 
@@ -1856,7 +1859,7 @@ pub mod error {
                     }
                 },
                 Self::Cast(e) => match e {
-                    CastError::Full => SporkSeverity::Transient,
+                    CastError::Full | CastError::DeadlineExceeded => SporkSeverity::Transient,
                     CastError::ServerStopped | CastError::Cancelled(_) => SporkSeverity::Permanent,
                 },
                 Self::Info(e) => match e {
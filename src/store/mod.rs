@@ -0,0 +1,1455 @@
+//! Embedded log-structured key-value store for runtime-local durable state.
+//!
+//! [`KvStore`] keeps small amounts of state that must survive a restart
+//! (idempotency keys, relay cursors, session journals) without each
+//! feature inventing its own file format. Keys and values are byte strings.
+//! Every commit appends one checksummed record to the active segment file
+//! in the store's directory; an in-memory index maps each live key to
+//! where its value sits on disk, so reads cost one positioned read and
+//! opening the store costs one scan of the segments.
+//!
+//! The store is intentionally small: [`KvStore::batch`] commits several
+//! puts and deletes atomically, and that is the only transaction there is.
+//! There are no secondary indexes and no query language.
+//!
+//! All file access goes through a [`Vfs`], so the same code runs on the real
+//! filesystem ([`UnixVfs`](crate::fs::UnixVfs)) and on the lab's
+//! [`SimFs`](crate::lab::sim_fs::SimFs), which tests use to inject torn
+//! writes, lying fsyncs, and crashes.
+//!
+//! # Durability
+//!
+//! A batch's record carries a CRC-32 of its contents, so a batch is either
+//! recovered whole or not at all. When it is made durable depends on
+//! [`KvStoreConfig::sync`]:
+//!
+//! - [`SyncPolicy::Always`] syncs before every commit returns: a batch
+//!   that returned `Ok` survives a crash.
+//! - [`SyncPolicy::Interval`] syncs when a commit finds the last sync older
+//!   than the interval; commits since the last sync may be lost.
+//! - [`SyncPolicy::Never`] leaves syncing to segment rollover, compaction,
+//!   snapshots, and [`KvStore::sync`].
+//!
+//! Under every policy, what survives a crash is a prefix of the committed
+//! batches. A commit that returns an error did not take effect in this
+//! process, but if the error came from the fsync its record may still be
+//! found after a restart.
+//!
+//! # Recovery
+//!
+//! Opening a store scans every segment. A record that is incomplete or
+//! fails its checksum ends the segment: it and everything after it in that
+//! segment are discarded, the file is truncated to the last valid record,
+//! and each discarded range is listed in [`KvStore::recovery`]. Later
+//! segments are still loaded; the store only writes past a damaged record
+//! in a new segment.
+//!
+//! # Compaction
+//!
+//! Overwritten and deleted values stay in their segments until
+//! [`KvStore::compact`] rewrites the live data into one segment and removes
+//! the rest. Compaction copies in chunks of
+//! [`compaction_chunk_bytes`](KvStoreConfig::compaction_chunk_bytes),
+//! releasing the store between chunks so commits and reads continue, and
+//! acquires [`IoScheduler`](crate::fs::IoScheduler) permits per chunk at
+//! normal priority while commits' fsyncs use the priority lane.
+//! [`KvStore::run_compaction`] is the background loop: spawn it as a task
+//! in the region that owns the store, and it compacts whenever the garbage
+//! ratio crosses [`compaction_garbage_ratio`](KvStoreConfig::compaction_garbage_ratio).
+//!
+//! # Snapshots
+//!
+//! [`KvStore::snapshot`] copies a consistent view of the store into a new
+//! directory that opens as a store of its own. Commits continue while it
+//! copies; compaction waits until it finishes.
+//!
+//! # Cancel Safety
+//!
+//! Every method can be cancelled. A commit cancelled before it returns did
+//! not take effect: the next commit cuts its partial record off the active
+//! segment before appending. A cancelled compaction removes its output and
+//! leaves the store as it was.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::fs::UnixVfs;
+//! use asupersync::store::{KvStore, KvStoreConfig, WriteBatch};
+//!
+//! let store = KvStore::open(UnixVfs::new(), "/var/lib/app/state", KvStoreConfig::default())
+//!     .await?;
+//! if !store.recovery().is_clean() {
+//!     warn!(report = ?store.recovery(), "state store discarded damaged records");
+//! }
+//!
+//! store.put(&cx, "relay/cursor", 42_u64.to_be_bytes()).await?;
+//! store
+//!     .batch(&cx, WriteBatch::new().put("job/7", "done").delete("pending/7"))
+//!     .await?;
+//! let pending = store.range(&cx, b"pending/".to_vec()..b"pending0".to_vec()).await?;
+//! ```
+
+mod segment;
+
+use self::segment::{HEADER_LEN, Op};
+use crate::cx::Cx;
+use crate::fs::io_scheduler::{IoClass, IoRequest, admit};
+use crate::fs::{OpenOptions, Vfs, VfsFile};
+use crate::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use crate::runtime::yield_now;
+use crate::sync::LockError;
+use crate::time::sleep;
+use crate::tracing_compat::{debug, warn};
+use crate::types::Time;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, SeekFrom};
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Suffix of a compaction's output while it is being written.
+const COMPACT_SUFFIX: &str = ".compact";
+
+/// When commits are synced to disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Sync before every commit returns.
+    #[default]
+    Always,
+    /// Sync when a commit finds the last sync at least this old.
+    Interval(Duration),
+    /// Never sync on commit.
+    Never,
+}
+
+/// Configuration for a [`KvStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KvStoreConfig {
+    /// When commits are synced.
+    pub sync: SyncPolicy,
+    /// Size at which the active segment is sealed and a new one started.
+    pub segment_bytes: u64,
+    /// Fraction of segment bytes that must be garbage before
+    /// [`KvStore::run_compaction`] compacts.
+    pub compaction_garbage_ratio: f64,
+    /// Total segment bytes below which the background loop never compacts.
+    pub compaction_min_bytes: u64,
+    /// Value bytes compaction copies between releasing the store.
+    pub compaction_chunk_bytes: usize,
+}
+
+impl Default for KvStoreConfig {
+    fn default() -> Self {
+        Self {
+            sync: SyncPolicy::Always,
+            segment_bytes: 64 * 1024 * 1024,
+            compaction_garbage_ratio: 0.5,
+            compaction_min_bytes: 4 * 1024 * 1024,
+            compaction_chunk_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Errors from a [`KvStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum KvError {
+    /// The filesystem failed; the operation did not take effect.
+    #[error("store I/O failed: {0}")]
+    Io(#[from] io::Error),
+    /// Waiting for the store was cancelled or failed.
+    #[error("store lock failed: {0}")]
+    Lock(#[from] LockError),
+    /// A batch does not fit in one record.
+    #[error("batch of {bytes} bytes exceeds the 4 GiB record limit")]
+    BatchTooLarge {
+        /// Encoded size of the batch.
+        bytes: usize,
+    },
+    /// The operation was cancelled part way; it did not take effect.
+    #[error("store operation cancelled")]
+    Cancelled,
+}
+
+/// Puts and deletes committed atomically by [`KvStore::batch`].
+///
+/// Operations apply in order, so a later operation on the same key wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteBatch {
+    ops: Vec<Op>,
+}
+
+impl WriteBatch {
+    /// Creates an empty batch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `key` to `value`.
+    #[must_use]
+    pub fn put(mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) -> Self {
+        self.ops.push(Op::Put(key.into(), value.into()));
+        self
+    }
+
+    /// Removes `key`.
+    #[must_use]
+    pub fn delete(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.ops.push(Op::Delete(key.into()));
+        self
+    }
+
+    /// Number of operations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns true if the batch has no operations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+/// Data dropped while opening a store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscardedData {
+    /// Segment the data was in.
+    pub segment: u64,
+    /// Offset of the first discarded byte.
+    pub offset: u64,
+    /// Number of bytes discarded.
+    pub bytes: u64,
+    /// Why the data could not be used.
+    pub reason: String,
+}
+
+/// What [`KvStore::open`] found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Segments loaded.
+    pub segments: usize,
+    /// Batch records replayed.
+    pub records: u64,
+    /// Live keys after replay.
+    pub keys: usize,
+    /// Damaged data that was dropped.
+    pub discarded: Vec<DiscardedData>,
+    /// Segments removed because a compacted segment already replaced them
+    /// (a compaction was interrupted before its cleanup).
+    pub superseded: Vec<u64>,
+}
+
+impl RecoveryReport {
+    /// Returns true if nothing was discarded.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.discarded.is_empty()
+    }
+}
+
+/// Outcome of one [`KvStore::compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Segments rewritten into one.
+    pub segments: usize,
+    /// Live keys copied.
+    pub keys: usize,
+    /// Segment bytes before compacting.
+    pub bytes_before: u64,
+    /// Size of the compacted segment.
+    pub bytes_after: u64,
+}
+
+/// Outcome of a [`KvStore::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotReport {
+    /// Segment files copied.
+    pub segments: usize,
+    /// Bytes copied.
+    pub bytes: u64,
+}
+
+/// Size of a [`KvStore`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KvStats {
+    /// Live keys.
+    pub keys: usize,
+    /// Segment files.
+    pub segments: usize,
+    /// Total segment bytes.
+    pub bytes: u64,
+    /// Bytes holding live keys and values.
+    pub live_bytes: u64,
+}
+
+impl KvStats {
+    /// Fraction of segment bytes not holding live data.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn garbage_ratio(&self) -> f64 {
+        if self.bytes == 0 {
+            0.0
+        } else {
+            1.0 - self.live_bytes as f64 / self.bytes as f64
+        }
+    }
+}
+
+/// Where a live value is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    segment: u64,
+    offset: u64,
+    len: u32,
+    /// Bytes of the put operation, counted as live in its segment.
+    footprint: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    bytes: u64,
+    live: u64,
+}
+
+struct Active<F> {
+    id: u64,
+    file: F,
+    /// End of the last committed record.
+    len: u64,
+    /// Committed records not yet synced.
+    dirty: bool,
+    /// A commit started writing and has not finished.
+    in_doubt: bool,
+}
+
+struct State<F> {
+    index: BTreeMap<Vec<u8>, Location>,
+    segments: BTreeMap<u64, Usage>,
+    active: Option<Active<F>>,
+    readers: BTreeMap<u64, F>,
+    next_segment: u64,
+    last_sync: Time,
+}
+
+impl<F> State<F> {
+    fn apply(&mut self, key: Vec<u8>, location: Option<Location>) {
+        let previous = match location {
+            Some(location) => {
+                self.segments.entry(location.segment).or_default().live += location.footprint;
+                self.index.insert(key, location)
+            }
+            None => self.index.remove(&key),
+        };
+        if let Some(previous) = previous
+            && let Some(usage) = self.segments.get_mut(&previous.segment)
+        {
+            usage.live -= previous.footprint;
+        }
+    }
+
+    fn stats(&self) -> KvStats {
+        KvStats {
+            keys: self.index.len(),
+            segments: self.segments.len(),
+            bytes: self.segments.values().map(|usage| usage.bytes).sum(),
+            live_bytes: self.segments.values().map(|usage| usage.live).sum(),
+        }
+    }
+}
+
+/// Keeps compaction from removing segments a snapshot is copying.
+struct SnapshotPin<'a>(&'a AtomicUsize);
+
+impl Drop for SnapshotPin<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Log-structured key-value store in one directory. See the
+/// [module docs](self).
+pub struct KvStore<V: Vfs> {
+    vfs: V,
+    dir: PathBuf,
+    config: KvStoreConfig,
+    state: crate::sync::Mutex<State<V::File>>,
+    compaction: crate::sync::Mutex<()>,
+    snapshots: AtomicUsize,
+    recovery: RecoveryReport,
+}
+
+impl<V: Vfs> fmt::Debug for KvStore<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KvStore")
+            .field("dir", &self.dir)
+            .field("config", &self.config)
+            .field("recovery", &self.recovery)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V: Vfs> KvStore<V> {
+    /// Opens the store in `dir`, creating the directory if needed, and
+    /// recovers its contents. See [`KvStore::recovery`] for what was found.
+    pub async fn open(
+        vfs: V,
+        dir: impl Into<PathBuf>,
+        config: KvStoreConfig,
+    ) -> Result<Self, KvError> {
+        let dir = dir.into();
+        vfs.create_dir_all(&dir).await?;
+        let mut ids = Vec::new();
+        let mut entries = vfs.read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if let Some(id) = segment::parse_file_name(&name) {
+                ids.push(id);
+            } else if name.ends_with(COMPACT_SUFFIX) {
+                // Output of a compaction that never finished.
+                vfs.remove_file(&entry.path()).await?;
+            }
+        }
+        ids.sort_unstable();
+        let (state, recovery) = Self::recover(&vfs, &dir, &ids).await?;
+        debug!(
+            dir = %dir.display(),
+            segments = recovery.segments,
+            records = recovery.records,
+            keys = recovery.keys,
+            discarded = recovery.discarded.len(),
+            "store recovered"
+        );
+        Ok(Self {
+            vfs,
+            dir,
+            config,
+            state: crate::sync::Mutex::new(state),
+            compaction: crate::sync::Mutex::new(()),
+            snapshots: AtomicUsize::new(0),
+            recovery,
+        })
+    }
+
+    async fn recover(
+        vfs: &V,
+        dir: &Path,
+        ids: &[u64],
+    ) -> Result<(State<V::File>, RecoveryReport), KvError> {
+        let mut report = RecoveryReport::default();
+        let mut headers = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let path = dir.join(segment::file_name(id));
+            match read_header(vfs, &path).await? {
+                Ok(covers_from) => headers.push((id, covers_from)),
+                Err(reason) => {
+                    let bytes = vfs.metadata(&path).await?.len();
+                    vfs.remove_file(&path).await?;
+                    // A segment created just before a crash has nothing to lose.
+                    if bytes > 0 {
+                        warn!(
+                            path = %path.display(),
+                            bytes,
+                            reason = %reason,
+                            "store discarded a segment with a damaged header"
+                        );
+                        report.discarded.push(DiscardedData {
+                            segment: id,
+                            offset: 0,
+                            bytes,
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+
+        // A compacted segment stands in for every segment from the one it
+        // covers up to itself; any of those still present are leftovers.
+        let mut covered_from = u64::MAX;
+        let mut kept = Vec::with_capacity(headers.len());
+        for &(id, covers_from) in headers.iter().rev() {
+            if id >= covered_from {
+                vfs.remove_file(&dir.join(segment::file_name(id))).await?;
+                report.superseded.push(id);
+            } else {
+                kept.push(id);
+                covered_from = covered_from.min(covers_from);
+            }
+        }
+        report.superseded.reverse();
+
+        let mut state = State {
+            index: BTreeMap::new(),
+            segments: BTreeMap::new(),
+            active: None,
+            readers: BTreeMap::new(),
+            next_segment: ids.last().map_or(0, |id| id + 1),
+            last_sync: Time::ZERO,
+        };
+        for &id in kept.iter().rev() {
+            let path = dir.join(segment::file_name(id));
+            let contents = vfs.read(&path).await?;
+            let scan = segment::scan(&contents);
+            for ops in scan.records {
+                for op in ops {
+                    let location = op.value.map(|(offset, len)| Location {
+                        segment: id,
+                        offset,
+                        len,
+                        footprint: op.footprint,
+                    });
+                    state.apply(op.key, location);
+                }
+                report.records += 1;
+            }
+            state.segments.entry(id).or_default().bytes = scan.valid_end;
+            if let Some(reason) = scan.damage {
+                let file = vfs.open(&path, &OpenOptions::new().write(true)).await?;
+                file.set_len(scan.valid_end).await?;
+                file.sync_all().await?;
+                let bytes = contents.len() as u64 - scan.valid_end;
+                warn!(
+                    path = %path.display(),
+                    offset = scan.valid_end,
+                    bytes,
+                    reason = %reason,
+                    "store discarded the damaged tail of a segment"
+                );
+                report.discarded.push(DiscardedData {
+                    segment: id,
+                    offset: scan.valid_end,
+                    bytes,
+                    reason,
+                });
+            }
+        }
+        report.segments = state.segments.len();
+        report.keys = state.index.len();
+        Ok((state, report))
+    }
+
+    /// What opening the store found and discarded.
+    #[must_use]
+    pub const fn recovery(&self) -> &RecoveryReport {
+        &self.recovery
+    }
+
+    /// The store's directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the value of `key`.
+    pub async fn get(&self, cx: &Cx, key: &[u8]) -> Result<Option<Vec<u8>>, KvError> {
+        let mut state = self.state.lock(cx).await?;
+        let Some(location) = state.index.get(key).copied() else {
+            return Ok(None);
+        };
+        Ok(Some(self.read_value(&mut state, location).await?))
+    }
+
+    /// Returns the entries whose keys fall in `range`, in key order.
+    pub async fn range<R>(&self, cx: &Cx, range: R) -> Result<Vec<(Vec<u8>, Vec<u8>)>, KvError>
+    where
+        R: RangeBounds<Vec<u8>>,
+    {
+        let mut state = self.state.lock(cx).await?;
+        let locations: Vec<(Vec<u8>, Location)> = state
+            .index
+            .range(range)
+            .map(|(key, location)| (key.clone(), *location))
+            .collect();
+        let mut entries = Vec::with_capacity(locations.len());
+        for (key, location) in locations {
+            let value = self.read_value(&mut state, location).await?;
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+
+    /// Sets `key` to `value`.
+    pub async fn put(
+        &self,
+        cx: &Cx,
+        key: impl Into<Vec<u8>>,
+        value: impl Into<Vec<u8>>,
+    ) -> Result<(), KvError> {
+        self.batch(cx, WriteBatch::new().put(key, value)).await
+    }
+
+    /// Removes `key`.
+    pub async fn delete(&self, cx: &Cx, key: impl Into<Vec<u8>>) -> Result<(), KvError> {
+        self.batch(cx, WriteBatch::new().delete(key)).await
+    }
+
+    /// Commits every operation of `batch`, or none of them.
+    pub async fn batch(&self, cx: &Cx, batch: WriteBatch) -> Result<(), KvError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let (record, placed) = segment::encode_record(&batch.ops)
+            .map_err(|bytes| KvError::BatchTooLarge { bytes })?;
+        let mut state = self.state.lock(cx).await?;
+        let (segment, offset) = self.append(&mut state, &record, cx.now()).await?;
+        for (op, placed) in batch.ops.into_iter().zip(placed) {
+            let location = placed.value.map(|(at, len)| Location {
+                segment,
+                offset: offset + at,
+                len,
+                footprint: placed.footprint,
+            });
+            state.apply(op.into_key(), location);
+        }
+        Ok(())
+    }
+
+    /// Syncs every committed batch to disk.
+    pub async fn sync(&self, cx: &Cx) -> Result<(), KvError> {
+        let mut state = self.state.lock(cx).await?;
+        if state.active.as_ref().is_some_and(|active| active.in_doubt) {
+            // Cutting the partial record off syncs the rest.
+            self.abandon(&mut state).await;
+            return Ok(());
+        }
+        self.sync_active(&mut state, cx.now()).await?;
+        Ok(())
+    }
+
+    /// Returns the store's size.
+    pub async fn stats(&self, cx: &Cx) -> Result<KvStats, KvError> {
+        Ok(self.state.lock(cx).await?.stats())
+    }
+
+    /// Rewrites the live data of every segment into one and removes the
+    /// others. Returns `None` if there was nothing to compact or a snapshot
+    /// is in progress.
+    pub async fn compact(&self, cx: &Cx) -> Result<Option<CompactionReport>, KvError> {
+        let _running = self.compaction.lock(cx).await?;
+        let (sealed, live) = {
+            let mut state = self.state.lock(cx).await?;
+            if self.snapshots.load(Ordering::Acquire) > 0 || state.segments.is_empty() {
+                return Ok(None);
+            }
+            // New commits go to a fresh segment from here on.
+            self.seal(&mut state, cx.now()).await?;
+            let sealed: Vec<(u64, u64)> = state
+                .segments
+                .iter()
+                .map(|(&id, usage)| (id, usage.bytes))
+                .collect();
+            let live: Vec<(Vec<u8>, Location)> = state
+                .index
+                .iter()
+                .map(|(key, location)| (key.clone(), *location))
+                .collect();
+            (sealed, live)
+        };
+        let (first, last) = (sealed[0].0, sealed[sealed.len() - 1].0);
+        let staging = self
+            .dir
+            .join(format!("{}{COMPACT_SUFFIX}", segment::file_name(last)));
+
+        let written = self.write_compacted(cx, &staging, first, last, &live).await;
+        let (moved, bytes_after) = match written {
+            Ok(written) => written,
+            Err(err) => {
+                let _ = self.vfs.remove_file(&staging).await;
+                return Err(err);
+            }
+        };
+        let mut state = match self.state.lock(cx).await {
+            Ok(state) => state,
+            Err(err) => {
+                let _ = self.vfs.remove_file(&staging).await;
+                return Err(err.into());
+            }
+        };
+        if self.snapshots.load(Ordering::Acquire) > 0 {
+            drop(state);
+            self.vfs.remove_file(&staging).await?;
+            return Ok(None);
+        }
+        if let Err(err) = self.vfs.rename(&staging, &self.segment_path(last)).await {
+            drop(state);
+            let _ = self.vfs.remove_file(&staging).await;
+            return Err(err.into());
+        }
+        // The compacted segment now stands in for all the sealed ones.
+        for &(id, _) in &sealed {
+            state.readers.remove(&id);
+            state.segments.remove(&id);
+        }
+        let mut live_bytes = 0;
+        for ((key, old), new) in live.iter().zip(moved) {
+            // Keys committed again while compacting keep their newer value.
+            if let Some(location) = state.index.get_mut(key)
+                && *location == *old
+            {
+                *location = new;
+                live_bytes += new.footprint;
+            }
+        }
+        state.segments.insert(
+            last,
+            Usage {
+                bytes: bytes_after,
+                live: live_bytes,
+            },
+        );
+        drop(state);
+
+        for &(id, _) in &sealed[..sealed.len() - 1] {
+            self.vfs.remove_file(&self.segment_path(id)).await?;
+        }
+        let report = CompactionReport {
+            segments: sealed.len(),
+            keys: live.len(),
+            bytes_before: sealed.iter().map(|&(_, bytes)| bytes).sum(),
+            bytes_after,
+        };
+        debug!(
+            dir = %self.dir.display(),
+            segments = report.segments,
+            keys = report.keys,
+            bytes_before = report.bytes_before,
+            bytes_after = report.bytes_after,
+            "store compacted"
+        );
+        Ok(Some(report))
+    }
+
+    /// Writes the compacted segment, returning where each live value went
+    /// and the segment's size.
+    async fn write_compacted(
+        &self,
+        cx: &Cx,
+        staging: &Path,
+        first: u64,
+        last: u64,
+        live: &[(Vec<u8>, Location)],
+    ) -> Result<(Vec<Location>, u64), KvError> {
+        let opts = OpenOptions::new().write(true).create(true).truncate(true);
+        let mut output = self.vfs.open(staging, &opts).await?;
+        output.write_all(&segment::encode_header(first)).await?;
+        let mut len = HEADER_LEN;
+        let mut readers = BTreeMap::new();
+        let mut moved = Vec::with_capacity(live.len());
+        let mut start = 0;
+        while start < live.len() {
+            cx.checkpoint().map_err(|_| KvError::Cancelled)?;
+            let mut end = start;
+            let mut chunk_bytes = 0;
+            while end < live.len()
+                && (end == start || chunk_bytes < self.config.compaction_chunk_bytes)
+            {
+                chunk_bytes += live[end].0.len() + live[end].1.len as usize;
+                end += 1;
+            }
+            let chunk = &live[start..end];
+            let request = IoRequest::read(&self.dir).with(staging, IoClass::Write);
+            let _permit = admit(request).await;
+            let mut ops = Vec::with_capacity(chunk.len());
+            for (key, location) in chunk {
+                let path = self.segment_path(location.segment);
+                let value = read_at(&self.vfs, &mut readers, &path, *location).await?;
+                ops.push(Op::Put(key.clone(), value));
+            }
+            let (record, placed) =
+                segment::encode_record(&ops).map_err(|bytes| KvError::BatchTooLarge { bytes })?;
+            output.write_all(&record).await?;
+            for placed in placed {
+                let (at, value_len) = placed.value.expect("compaction writes only puts");
+                moved.push(Location {
+                    segment: last,
+                    offset: len + at,
+                    len: value_len,
+                    footprint: placed.footprint,
+                });
+            }
+            len += record.len() as u64;
+            start = end;
+            yield_now().await;
+        }
+        output.sync_all().await?;
+        Ok((moved, len))
+    }
+
+    /// Compacts whenever the garbage ratio calls for it, checking every
+    /// `interval`, until `cx` is cancelled. Spawn it as a task in the
+    /// region that owns the store.
+    pub async fn run_compaction(&self, cx: &Cx, interval: Duration) {
+        while cx.checkpoint().is_ok() {
+            let due = self.stats(cx).await.is_ok_and(|stats| {
+                stats.bytes >= self.config.compaction_min_bytes
+                    && stats.garbage_ratio() >= self.config.compaction_garbage_ratio
+            });
+            if due && let Err(_error) = self.compact(cx).await {
+                warn!(dir = %self.dir.display(), error = %_error, "store compaction failed");
+            }
+            sleep(cx.now(), interval).await;
+        }
+    }
+
+    /// Copies a consistent view of the store into `dest`, which must not
+    /// exist. The copy opens as a store of its own.
+    pub async fn snapshot(
+        &self,
+        cx: &Cx,
+        dest: impl AsRef<Path>,
+    ) -> Result<SnapshotReport, KvError> {
+        let dest = dest.as_ref();
+        if self.vfs.metadata(dest).await.is_ok() {
+            let message = format!("snapshot destination {} exists", dest.display());
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, message).into());
+        }
+        let (segments, _pin) = {
+            let mut state = self.state.lock(cx).await?;
+            // Sealed segments do not change until compaction, which the
+            // pin holds off.
+            self.seal(&mut state, cx.now()).await?;
+            self.snapshots.fetch_add(1, Ordering::AcqRel);
+            let pin = SnapshotPin(&self.snapshots);
+            let segments: Vec<(u64, u64)> = state
+                .segments
+                .iter()
+                .map(|(&id, usage)| (id, usage.bytes))
+                .collect();
+            (segments, pin)
+        };
+        self.vfs.create_dir_all(dest).await?;
+        let mut bytes = 0;
+        for &(id, len) in &segments {
+            cx.checkpoint().map_err(|_| KvError::Cancelled)?;
+            let source = self.segment_path(id);
+            let target = dest.join(segment::file_name(id));
+            let _permit = admit(IoRequest::read(&source).with(&target, IoClass::Write)).await;
+            let mut contents = self.vfs.read(&source).await?;
+            contents.truncate(len as usize);
+            let mut file = self.vfs.open_create(&target).await?;
+            file.write_all(&contents).await?;
+            file.sync_all().await?;
+            bytes += len;
+        }
+        Ok(SnapshotReport {
+            segments: segments.len(),
+            bytes,
+        })
+    }
+
+    fn segment_path(&self, id: u64) -> PathBuf {
+        self.dir.join(segment::file_name(id))
+    }
+
+    async fn read_value(
+        &self,
+        state: &mut State<V::File>,
+        location: Location,
+    ) -> Result<Vec<u8>, KvError> {
+        let path = self.segment_path(location.segment);
+        let _permit = admit(IoRequest::read(&path)).await;
+        Ok(read_at(&self.vfs, &mut state.readers, &path, location).await?)
+    }
+
+    /// Appends `record` to the active segment, returning the segment and
+    /// the record's offset in it.
+    async fn append(
+        &self,
+        state: &mut State<V::File>,
+        record: &[u8],
+        now: Time,
+    ) -> Result<(u64, u64), KvError> {
+        if state.active.as_ref().is_some_and(|active| active.in_doubt) {
+            // A cancelled commit may have left part of its record behind.
+            self.abandon(state).await;
+        }
+        let len = record.len() as u64;
+        let full = state.active.as_ref().is_some_and(|active| {
+            active.len > HEADER_LEN && active.len + len > self.config.segment_bytes
+        });
+        if full {
+            self.seal(state, now).await?;
+        }
+        if state.active.is_none() {
+            let active = self.create_segment(state).await?;
+            state.active = Some(active);
+        }
+        let active = state.active.as_mut().expect("active segment");
+        let (id, offset) = (active.id, active.len);
+        active.in_doubt = true;
+        let path = self.segment_path(id);
+        let written = {
+            let _permit = admit(IoRequest::write(&path)).await;
+            active.file.write_all(record).await
+        };
+        if let Err(err) = written {
+            self.abandon(state).await;
+            return Err(err.into());
+        }
+        active.dirty = true;
+        let sync = match self.config.sync {
+            SyncPolicy::Always => true,
+            SyncPolicy::Interval(interval) => {
+                let interval = u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX);
+                now.duration_since(state.last_sync) >= interval
+            }
+            SyncPolicy::Never => false,
+        };
+        if sync && let Err(err) = self.sync_active(state, now).await {
+            self.abandon(state).await;
+            return Err(err.into());
+        }
+        if let Some(active) = state.active.as_mut() {
+            active.len = offset + len;
+            active.in_doubt = false;
+        }
+        state.segments.entry(id).or_default().bytes = offset + len;
+        Ok((id, offset))
+    }
+
+    async fn create_segment(&self, state: &mut State<V::File>) -> Result<Active<V::File>, KvError> {
+        let id = state.next_segment;
+        state.next_segment += 1;
+        let path = self.segment_path(id);
+        let opts = OpenOptions::new().append(true).create_new(true);
+        let mut file = self.vfs.open(&path, &opts).await?;
+        let header = segment::encode_header(id);
+        let written = async {
+            file.write_all(&header).await?;
+            file.sync_all().await
+        };
+        if let Err(err) = written.await {
+            let _ = self.vfs.remove_file(&path).await;
+            return Err(err.into());
+        }
+        state.segments.insert(
+            id,
+            Usage {
+                bytes: HEADER_LEN,
+                live: 0,
+            },
+        );
+        Ok(Active {
+            id,
+            file,
+            len: HEADER_LEN,
+            dirty: false,
+            in_doubt: false,
+        })
+    }
+
+    async fn sync_active(&self, state: &mut State<V::File>, now: Time) -> io::Result<()> {
+        if let Some(active) = state.active.as_mut()
+            && active.dirty
+        {
+            let path = self.segment_path(active.id);
+            let _permit = admit(IoRequest::fsync(path).critical()).await;
+            active.file.sync_data().await?;
+            active.dirty = false;
+        }
+        state.last_sync = now;
+        Ok(())
+    }
+
+    /// Stops appending to the active segment. Its committed records are
+    /// synced first, so a later segment never survives a crash that an
+    /// earlier one does not.
+    async fn seal(&self, state: &mut State<V::File>, now: Time) -> Result<(), KvError> {
+        let Some(in_doubt) = state.active.as_ref().map(|active| active.in_doubt) else {
+            return Ok(());
+        };
+        if in_doubt {
+            self.abandon(state).await;
+        } else {
+            self.sync_active(state, now).await?;
+            state.active = None;
+        }
+        Ok(())
+    }
+
+    /// Gives up the active segment after a failed or cancelled commit,
+    /// cutting it back to its last committed record; the next commit
+    /// starts a new segment.
+    async fn abandon(&self, state: &mut State<V::File>) {
+        let Some(active) = state.active.take() else {
+            return;
+        };
+        let cut = async {
+            active.file.set_len(active.len).await?;
+            active.file.sync_data().await
+        };
+        if let Err(_error) = cut.await {
+            // Recovery discards the partial record instead.
+            warn!(
+                segment = active.id,
+                error = %_error,
+                "store could not truncate a failed commit"
+            );
+        }
+    }
+}
+
+/// Reads a segment header, returning `covers_from` or why it is unusable.
+async fn read_header<V: Vfs>(vfs: &V, path: &Path) -> io::Result<Result<u64, String>> {
+    let mut file = vfs.open_read(path).await?;
+    let mut header = vec![0; HEADER_LEN as usize];
+    match file.read_exact(&mut header).await {
+        Ok(()) => Ok(segment::decode_header(&header)),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            Ok(Err("segment has no complete header".to_string()))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Reads the value at `location` from the segment at `path`, opening and
+/// caching a handle for it.
+async fn read_at<V: Vfs>(
+    vfs: &V,
+    readers: &mut BTreeMap<u64, V::File>,
+    path: &Path,
+    location: Location,
+) -> io::Result<Vec<u8>> {
+    if !readers.contains_key(&location.segment) {
+        let file = vfs.open_read(path).await?;
+        readers.insert(location.segment, file);
+    }
+    let file = readers
+        .get_mut(&location.segment)
+        .expect("reader just opened");
+    file.seek(SeekFrom::Start(location.offset)).await?;
+    let mut value = vec![0; location.len as usize];
+    file.read_exact(&mut value).await?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::lab::sim_fs::{SimFs, SimFsConfig, SimFsFailure, SimFsFault, SimFsOp};
+    use crate::util::DetRng;
+    use futures_lite::future::{block_on, zip};
+
+    const DIR: &str = "/kv";
+
+    type Contents = BTreeMap<Vec<u8>, Vec<u8>>;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn config(segment_bytes: u64) -> KvStoreConfig {
+        KvStoreConfig {
+            segment_bytes,
+            compaction_chunk_bytes: 64,
+            ..KvStoreConfig::default()
+        }
+    }
+
+    async fn open(fs: &SimFs, config: KvStoreConfig) -> KvStore<SimFs> {
+        KvStore::open(fs.clone(), DIR, config)
+            .await
+            .expect("open store")
+    }
+
+    async fn contents(store: &KvStore<SimFs>, cx: &Cx) -> Contents {
+        store.range(cx, ..).await.expect("range").into_iter().collect()
+    }
+
+    fn entries(pairs: &[(&str, &str)]) -> Contents {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+            .collect()
+    }
+
+    /// A random batch over a small key space, and the model after it.
+    fn random_batch(rng: &mut DetRng, model: &Contents, tag: usize) -> (WriteBatch, Contents) {
+        let mut batch = WriteBatch::new();
+        let mut next = model.clone();
+        for op in 0..1 + rng.next_usize(4) {
+            let key = format!("k{:02}", rng.next_usize(16)).into_bytes();
+            if rng.next_usize(4) == 0 {
+                next.remove(&key);
+                batch = batch.delete(key);
+            } else {
+                let value = format!("{tag}.{op}:{}", "x".repeat(rng.next_usize(48))).into_bytes();
+                next.insert(key.clone(), value.clone());
+                batch = batch.put(key, value);
+            }
+        }
+        (batch, next)
+    }
+
+    #[test]
+    fn range_is_key_ordered_and_survives_restart() {
+        init_test("range_is_key_ordered_and_survives_restart");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(4096)).await;
+            for key in ["m", "a", "z", "c", "b"] {
+                store.put(&cx, key, key.to_uppercase()).await.expect("put");
+            }
+            store.delete(&cx, "z").await.expect("delete");
+            store.put(&cx, "a", "A2").await.expect("overwrite");
+
+            let window = store
+                .range(&cx, b"b".to_vec()..b"n".to_vec())
+                .await
+                .expect("range");
+            let keys: Vec<&[u8]> = window.iter().map(|(key, _)| key.as_slice()).collect();
+            let expected: Vec<&[u8]> = vec![b"b", b"c", b"m"];
+            crate::assert_with_log!(keys == expected, "range order", expected, keys);
+            let deleted = store.get(&cx, b"z").await.expect("get");
+            crate::assert_with_log!(deleted.is_none(), "deleted", "None", deleted);
+
+            drop(store);
+            fs.inject(SimFsFault::Crash);
+            let store = open(&fs, config(4096)).await;
+            let recovered = contents(&store, &cx).await;
+            let expected = entries(&[("a", "A2"), ("b", "B"), ("c", "C"), ("m", "M")]);
+            crate::assert_with_log!(recovered == expected, "recovered", expected, recovered);
+            let clean = store.recovery().is_clean();
+            crate::assert_with_log!(clean, "clean recovery", true, store.recovery());
+        });
+        crate::test_complete!("range_is_key_ordered_and_survives_restart");
+    }
+
+    #[test]
+    fn torn_batch_applies_nothing() {
+        init_test("torn_batch_applies_nothing");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(4096)).await;
+            store.put(&cx, "balance/a", "100").await.expect("put a");
+            store.put(&cx, "balance/b", "0").await.expect("put b");
+            store.put(&cx, "pending/1", "a->b").await.expect("put pending");
+
+            let transfer = WriteBatch::new()
+                .put("balance/a", "40")
+                .put("balance/b", "60")
+                .delete("pending/1");
+            fs.inject(SimFsFault::Arm {
+                glob: format!("{DIR}/*.seg"),
+                failure: SimFsFailure::TornWrite,
+                count: 1,
+            });
+            let failed = store.batch(&cx, transfer.clone()).await.is_err();
+            crate::assert_with_log!(failed, "torn batch fails", true, failed);
+            let unchanged = contents(&store, &cx).await;
+            let expected = entries(&[
+                ("balance/a", "100"),
+                ("balance/b", "0"),
+                ("pending/1", "a->b"),
+            ]);
+            crate::assert_with_log!(unchanged == expected, "nothing applied", expected, unchanged);
+
+            store.batch(&cx, transfer).await.expect("retry");
+            store.put(&cx, "audit", "1").await.expect("later commit");
+            drop(store);
+            fs.inject(SimFsFault::Crash);
+
+            let store = open(&fs, config(4096)).await;
+            let recovered = contents(&store, &cx).await;
+            let expected = entries(&[("audit", "1"), ("balance/a", "40"), ("balance/b", "60")]);
+            crate::assert_with_log!(recovered == expected, "recovered", expected, recovered);
+            // The partial record was cut off before the next commit.
+            let clean = store.recovery().is_clean();
+            crate::assert_with_log!(clean, "clean recovery", true, store.recovery());
+        });
+        crate::test_complete!("torn_batch_applies_nothing");
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Fault {
+        None,
+        TornWrite,
+        /// The torn write and the truncation after it both fail.
+        TornWriteUntruncated,
+        FsyncLie,
+    }
+
+    /// Runs random batches, injects `fault` at a random batch, crashes, and
+    /// returns the committed states, the oldest one that must have
+    /// survived, and what was recovered.
+    fn crash_case(
+        seed: u64,
+        fault: Fault,
+        crash_at_fault: bool,
+    ) -> (Vec<Contents>, usize, Contents) {
+        let fs = SimFs::new(SimFsConfig::new(seed));
+        let cx = Cx::for_testing();
+        // A lying fsync at a rollover can make a later segment durable
+        // before an earlier one; keep that case to one segment.
+        let segment_bytes = if fault == Fault::FsyncLie { 1 << 20 } else { 512 };
+        block_on(async {
+            let mut rng = DetRng::new(seed);
+            let store = open(&fs, config(segment_bytes)).await;
+            let mut committed = vec![Contents::new()];
+            let mut floor = 0;
+            let fault_at = 8 + rng.next_usize(16);
+            for tag in 0..40 {
+                let injected = tag == fault_at;
+                if injected {
+                    let (failure, count) = match fault {
+                        Fault::None => (None, 0),
+                        Fault::TornWrite => (Some(SimFsFailure::TornWrite), 1),
+                        Fault::TornWriteUntruncated => (Some(SimFsFailure::TornWrite), 2),
+                        Fault::FsyncLie => (Some(SimFsFailure::FsyncLie), 1),
+                    };
+                    if let Some(failure) = failure {
+                        fs.inject(SimFsFault::Arm {
+                            glob: format!("{DIR}/*.seg"),
+                            failure,
+                            count,
+                        });
+                    }
+                }
+                let model = committed.last().expect("initial state").clone();
+                let (batch, next) = random_batch(&mut rng, &model, tag);
+                if store.batch(&cx, batch).await.is_ok() {
+                    committed.push(next);
+                    if !(injected && fault == Fault::FsyncLie) {
+                        floor = committed.len() - 1;
+                    }
+                }
+                if injected && crash_at_fault {
+                    break;
+                }
+            }
+            drop(store);
+            fs.inject(SimFsFault::Crash);
+            let store = open(&fs, config(segment_bytes)).await;
+            let recovered = contents(&store, &cx).await;
+            (committed, floor, recovered)
+        })
+    }
+
+    #[test]
+    fn crash_matrix_recovers_a_committed_prefix() {
+        init_test("crash_matrix_recovers_a_committed_prefix");
+        let faults = [
+            Fault::None,
+            Fault::TornWrite,
+            Fault::TornWriteUntruncated,
+            Fault::FsyncLie,
+        ];
+        for seed in 0..6 {
+            for fault in faults {
+                for crash_at_fault in [false, true] {
+                    let (committed, floor, recovered) = crash_case(seed, fault, crash_at_fault);
+                    let case = format!("seed {seed} {fault:?} crash_at_fault={crash_at_fault}");
+                    let position = committed.iter().rposition(|state| *state == recovered);
+                    crate::assert_with_log!(
+                        position.is_some_and(|position| position >= floor),
+                        &case,
+                        format!("a committed state at or after #{floor}"),
+                        position
+                    );
+                    if fault != Fault::FsyncLie {
+                        let last = committed.len() - 1;
+                        crate::assert_with_log!(
+                            position == Some(last),
+                            &case,
+                            Some(last),
+                            position
+                        );
+                    }
+                }
+            }
+        }
+        crate::test_complete!("crash_matrix_recovers_a_committed_prefix");
+    }
+
+    #[test]
+    fn corrupt_record_discards_rest_of_its_segment_only() {
+        init_test("corrupt_record_discards_rest_of_its_segment_only");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(4096)).await;
+            for (key, value) in [("a", "1"), ("b", "2"), ("c", "3")] {
+                store.put(&cx, key, value).await.expect("put");
+            }
+            drop(store);
+            // The next process writes to a new segment.
+            let store = open(&fs, config(4096)).await;
+            store.put(&cx, "d", "4").await.expect("put d");
+            drop(store);
+
+            let first = Path::new(DIR).join(segment::file_name(0));
+            let mut bytes = fs.read(&first).await.expect("read segment");
+            let ops = [Op::Put(b"a".to_vec(), b"1".to_vec())];
+            let (record, _) = segment::encode_record(&ops).expect("encode");
+            let damaged_at = HEADER_LEN + record.len() as u64;
+            let flip = damaged_at as usize + 10;
+            bytes[flip] ^= 0xff;
+            fs.write(&first, &bytes).await.expect("corrupt segment");
+
+            let store = open(&fs, config(4096)).await;
+            let recovered = contents(&store, &cx).await;
+            let expected = entries(&[("a", "1"), ("d", "4")]);
+            crate::assert_with_log!(recovered == expected, "recovered", expected, recovered);
+            let discarded = store.recovery().discarded.clone();
+            let expected = vec![DiscardedData {
+                segment: 0,
+                offset: damaged_at,
+                bytes: bytes.len() as u64 - damaged_at,
+                reason: "record checksum mismatch".to_string(),
+            }];
+            crate::assert_with_log!(discarded == expected, "reported", expected, discarded);
+            drop(store);
+
+            let store = open(&fs, config(4096)).await;
+            let clean = store.recovery().is_clean();
+            crate::assert_with_log!(clean, "damage truncated", true, store.recovery());
+        });
+        crate::test_complete!("corrupt_record_discards_rest_of_its_segment_only");
+    }
+
+    #[test]
+    fn compaction_preserves_live_data_under_concurrent_writes() {
+        init_test("compaction_preserves_live_data_under_concurrent_writes");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(512)).await;
+            let mut rng = DetRng::new(7);
+            let mut model = Contents::new();
+            for tag in 0..200 {
+                let (batch, next) = random_batch(&mut rng, &model, tag);
+                store.batch(&cx, batch).await.expect("batch");
+                model = next;
+            }
+            let before = store.stats(&cx).await.expect("stats");
+            crate::assert_with_log!(before.segments > 5, "many segments", "> 5", before.segments);
+
+            let written = AtomicUsize::new(0);
+            let compaction = async {
+                let report = store.compact(&cx).await.expect("compact");
+                (report, written.load(Ordering::SeqCst))
+            };
+            let writer = async {
+                let mut model = model;
+                for tag in 200..260 {
+                    let (batch, next) = random_batch(&mut rng, &model, tag);
+                    store.batch(&cx, batch).await.expect("concurrent batch");
+                    model = next;
+                    written.fetch_add(1, Ordering::SeqCst);
+                    yield_now().await;
+                }
+                model
+            };
+            let ((report, during), model) = zip(compaction, writer).await;
+            let report = report.expect("compacted");
+            crate::assert_with_log!(
+                during > 0 && during < 60,
+                "writes interleaved with compaction",
+                "between 1 and 59",
+                during
+            );
+            crate::assert_with_log!(
+                report.segments == before.segments,
+                "merged every segment",
+                before.segments,
+                report.segments
+            );
+            let live = contents(&store, &cx).await;
+            crate::assert_with_log!(live == model, "live data", model, live);
+            let after = store.stats(&cx).await.expect("stats");
+            let shrunk = after.bytes < before.bytes;
+            crate::assert_with_log!(shrunk, "shrunk", before.bytes, after.bytes);
+
+            drop(store);
+            fs.inject(SimFsFault::Crash);
+            let store = open(&fs, config(512)).await;
+            let recovered = contents(&store, &cx).await;
+            crate::assert_with_log!(recovered == model, "after restart", model, recovered);
+        });
+        crate::test_complete!("compaction_preserves_live_data_under_concurrent_writes");
+    }
+
+    #[test]
+    fn interrupted_compaction_cleanup_finishes_on_open() {
+        init_test("interrupted_compaction_cleanup_finishes_on_open");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(256)).await;
+            let mut rng = DetRng::new(11);
+            let mut model = Contents::new();
+            for tag in 0..60 {
+                let (batch, next) = random_batch(&mut rng, &model, tag);
+                store.batch(&cx, batch).await.expect("batch");
+                model = next;
+            }
+            // Removing the first superseded segment fails after the
+            // compacted segment is in place.
+            fs.inject(SimFsFault::Arm {
+                glob: format!("{DIR}/{}", segment::file_name(0)),
+                failure: SimFsFailure::Io(SimFsOp::Metadata),
+                count: 1,
+            });
+            let failed = store.compact(&cx).await.is_err();
+            crate::assert_with_log!(failed, "cleanup failed", true, failed);
+            let live = contents(&store, &cx).await;
+            crate::assert_with_log!(live == model, "still serving", model, live);
+
+            drop(store);
+            fs.inject(SimFsFault::Crash);
+            let store = open(&fs, config(256)).await;
+            let superseded = store.recovery().superseded.first().copied();
+            crate::assert_with_log!(superseded == Some(0), "leftovers", Some(0), superseded);
+            let recovered = contents(&store, &cx).await;
+            crate::assert_with_log!(recovered == model, "recovered", model, recovered);
+        });
+        crate::test_complete!("interrupted_compaction_cleanup_finishes_on_open");
+    }
+
+    #[test]
+    fn snapshot_opens_as_point_in_time_copy() {
+        init_test("snapshot_opens_as_point_in_time_copy");
+        let fs = SimFs::default();
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = open(&fs, config(4096)).await;
+            store.put(&cx, "a", "1").await.expect("put a");
+            store.put(&cx, "b", "2").await.expect("put b");
+            let report = store.snapshot(&cx, "/backup").await.expect("snapshot");
+            crate::assert_with_log!(report.segments == 1, "segments", 1, report.segments);
+            store.put(&cx, "c", "3").await.expect("put c");
+            store.delete(&cx, "a").await.expect("delete a");
+
+            let copy = KvStore::open(fs.clone(), "/backup", config(4096))
+                .await
+                .expect("open snapshot");
+            let copied = contents(&copy, &cx).await;
+            let expected = entries(&[("a", "1"), ("b", "2")]);
+            crate::assert_with_log!(copied == expected, "snapshot", expected, copied);
+            let live = contents(&store, &cx).await;
+            let expected = entries(&[("b", "2"), ("c", "3")]);
+            crate::assert_with_log!(live == expected, "live", expected, live);
+
+            let again = store.snapshot(&cx, "/backup").await.is_err();
+            crate::assert_with_log!(again, "destination must be new", true, again);
+        });
+        crate::test_complete!("snapshot_opens_as_point_in_time_copy");
+    }
+}
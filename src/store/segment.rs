@@ -0,0 +1,255 @@
+//! On-disk segment format.
+//!
+//! A segment starts with a header and holds one record per committed batch:
+//!
+//! ```text
+//! header:  magic "ASKVSEG1" | covers_from u64 | crc32 u32      (20 bytes)
+//! record:  payload_len u32 | crc32(payload) u32 | payload
+//! payload: op_count u32 | op*
+//! op:      0x01 | key_len u32 | key | value_len u32 | value     (put)
+//!          0x02 | key_len u32 | key                             (delete)
+//! ```
+//!
+//! Integers are little-endian. `covers_from` is the segment's own id for
+//! segments written by commits; a compacted segment records the oldest
+//! segment it replaced, so segments it supersedes that survive a crash
+//! are recognized on open.
+
+use std::ops::Range;
+
+const MAGIC: &[u8; 8] = b"ASKVSEG1";
+/// Length of the segment header.
+pub(super) const HEADER_LEN: u64 = 20;
+/// Length of a record's framing, before the payload.
+const RECORD_HEADER_LEN: usize = 8;
+const TAG_PUT: u8 = 1;
+const TAG_DELETE: u8 = 2;
+
+/// File name of segment `id`.
+pub(super) fn file_name(id: u64) -> String {
+    format!("{id:016x}.seg")
+}
+
+/// Segment id encoded in `name`, if it names a segment.
+pub(super) fn parse_file_name(name: &str) -> Option<u64> {
+    let hex = name.strip_suffix(".seg")?;
+    if hex.len() != 16 {
+        return None;
+    }
+    u64::from_str_radix(hex, 16).ok()
+}
+
+pub(super) fn encode_header(covers_from: u64) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HEADER_LEN as usize);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&covers_from.to_le_bytes());
+    let crc = crc32fast::hash(&header);
+    header.extend_from_slice(&crc.to_le_bytes());
+    header
+}
+
+/// Decodes a segment header, returning `covers_from`.
+pub(super) fn decode_header(bytes: &[u8]) -> Result<u64, String> {
+    let Some(header) = bytes.get(..HEADER_LEN as usize) else {
+        return Err(format!("{}-byte segment has no complete header", bytes.len()));
+    };
+    if &header[..8] != MAGIC {
+        return Err("bad segment magic".to_string());
+    }
+    if crc32fast::hash(&header[..16]) != read_u32(header, 16) {
+        return Err("segment header checksum mismatch".to_string());
+    }
+    Ok(read_u64(header, 8))
+}
+
+/// One operation of a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Op {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+impl Op {
+    pub(super) fn into_key(self) -> Vec<u8> {
+        match self {
+            Self::Put(key, _) | Self::Delete(key) => key,
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Put(key, value) => 1 + 4 + key.len() + 4 + value.len(),
+            Self::Delete(key) => 1 + 4 + key.len(),
+        }
+    }
+}
+
+/// Where an encoded operation landed, relative to the start of its record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Placed {
+    /// Offset and length of the value for a put, `None` for a delete.
+    pub(super) value: Option<(u64, u32)>,
+    /// Bytes the operation occupies.
+    pub(super) footprint: u64,
+}
+
+/// Encodes `ops` as one record. Fails with the payload size when it does
+/// not fit the 32-bit length field.
+pub(super) fn encode_record(ops: &[Op]) -> Result<(Vec<u8>, Vec<Placed>), usize> {
+    let payload_len = 4 + ops.iter().map(Op::encoded_len).sum::<usize>();
+    let Ok(len) = u32::try_from(payload_len) else {
+        return Err(payload_len);
+    };
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + payload_len);
+    record.extend_from_slice(&len.to_le_bytes());
+    record.extend_from_slice(&[0; 4]);
+    // Every count and length fits: the whole payload length does.
+    record.extend_from_slice(&(ops.len() as u32).to_le_bytes());
+    let mut placed = Vec::with_capacity(ops.len());
+    for op in ops {
+        let start = record.len();
+        let (tag, key, value) = match op {
+            Op::Put(key, value) => (TAG_PUT, key, Some(value)),
+            Op::Delete(key) => (TAG_DELETE, key, None),
+        };
+        record.push(tag);
+        record.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        record.extend_from_slice(key);
+        let value = value.map(|value| {
+            record.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            let at = record.len() as u64;
+            record.extend_from_slice(value);
+            (at, value.len() as u32)
+        });
+        placed.push(Placed {
+            value,
+            footprint: (record.len() - start) as u64,
+        });
+    }
+    let crc = crc32fast::hash(&record[RECORD_HEADER_LEN..]);
+    record[4..RECORD_HEADER_LEN].copy_from_slice(&crc.to_le_bytes());
+    Ok((record, placed))
+}
+
+/// A decoded operation with its value located in the segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ScannedOp {
+    pub(super) key: Vec<u8>,
+    /// Absolute offset and length of the value for a put.
+    pub(super) value: Option<(u64, u32)>,
+    pub(super) footprint: u64,
+}
+
+/// Result of scanning a segment's records.
+#[derive(Debug, Default)]
+pub(super) struct Scan {
+    /// Operations of each intact record, in order.
+    pub(super) records: Vec<Vec<ScannedOp>>,
+    /// End of the last intact record.
+    pub(super) valid_end: u64,
+    /// Why scanning stopped before the end of the data, if it did.
+    pub(super) damage: Option<String>,
+}
+
+/// Scans the records of a segment whose full contents are `bytes`, stopping
+/// at the first record that is incomplete or fails its checksum.
+pub(super) fn scan(bytes: &[u8]) -> Scan {
+    let mut scan = Scan {
+        valid_end: HEADER_LEN,
+        ..Scan::default()
+    };
+    let mut at = HEADER_LEN as usize;
+    while at < bytes.len() {
+        match decode_record(bytes, at) {
+            Ok((ops, end)) => {
+                scan.records.push(ops);
+                at = end;
+                scan.valid_end = end as u64;
+            }
+            Err(reason) => {
+                scan.damage = Some(reason);
+                break;
+            }
+        }
+    }
+    scan
+}
+
+fn decode_record(bytes: &[u8], start: usize) -> Result<(Vec<ScannedOp>, usize), String> {
+    let remaining = bytes.len() - start;
+    if remaining < RECORD_HEADER_LEN {
+        return Err(format!("truncated record header ({remaining} bytes)"));
+    }
+    let len = read_u32(bytes, start) as usize;
+    let payload_start = start + RECORD_HEADER_LEN;
+    let end = payload_start
+        .checked_add(len)
+        .filter(|&end| end <= bytes.len())
+        .ok_or_else(|| format!("truncated record ({len}-byte payload)"))?;
+    let payload = &bytes[payload_start..end];
+    if crc32fast::hash(payload) != read_u32(bytes, start + 4) {
+        return Err("record checksum mismatch".to_string());
+    }
+    let malformed = || "malformed record payload".to_string();
+    let mut cursor = Cursor {
+        bytes: payload,
+        at: 0,
+    };
+    let count = cursor.u32().ok_or_else(malformed)?;
+    let mut ops = Vec::new();
+    for _ in 0..count {
+        let op_start = cursor.at;
+        let tag = payload[cursor.take(1).ok_or_else(malformed)?.start];
+        let key_len = cursor.u32().ok_or_else(malformed)?;
+        let key = payload[cursor.take(key_len as usize).ok_or_else(malformed)?].to_vec();
+        let value = match tag {
+            TAG_PUT => {
+                let value_len = cursor.u32().ok_or_else(malformed)?;
+                let value = cursor.take(value_len as usize).ok_or_else(malformed)?;
+                Some(((payload_start + value.start) as u64, value_len))
+            }
+            TAG_DELETE => None,
+            _ => return Err(format!("unknown operation tag {tag:#04x}")),
+        };
+        ops.push(ScannedOp {
+            key,
+            value,
+            footprint: (cursor.at - op_start) as u64,
+        });
+    }
+    if cursor.at != payload.len() {
+        return Err(malformed());
+    }
+    Ok((ops, end))
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Cursor<'_> {
+    fn take(&mut self, len: usize) -> Option<Range<usize>> {
+        let end = self.at.checked_add(len).filter(|&end| end <= self.bytes.len())?;
+        let range = self.at..end;
+        self.at = end;
+        Some(range)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let range = self.take(4)?;
+        Some(read_u32(self.bytes, range.start))
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&bytes[at..at + 4]);
+    u32::from_le_bytes(raw)
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    let mut raw = [0; 8];
+    raw.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(raw)
+}
@@ -44,6 +44,9 @@
 //! - [`Chunks`]: Groups items into fixed-size batches
 //! - [`ReadyChunks`]: Returns immediately available items
 //!
+//! ## Fan-out
+//! - [`partition_by_key`]: Splits a stream into ordered per-key partitions
+//!
 //! ## Terminal Operations
 //! - [`Collect`]: Collects all items into a collection
 //! - [`Fold`]: Reduces items into a single value
@@ -91,6 +94,7 @@ mod iter;
 mod map;
 mod merge;
 mod next;
+mod partition;
 mod peekable;
 mod receiver_stream;
 mod scan;
@@ -122,6 +126,7 @@ pub use iter::{Iter, iter};
 pub use map::Map;
 pub use merge::{Merge, merge};
 pub use next::Next;
+pub use partition::{KeyPartition, PartitionReport, PartitionRouter, partition_by_key};
 pub use peekable::Peekable;
 pub use receiver_stream::ReceiverStream;
 pub use scan::Scan;
@@ -135,6 +140,7 @@ pub use watch_stream::WatchStream;
 pub use zip::Zip;
 
 use std::future::Future;
+use std::hash::Hash;
 use std::time::Duration;
 
 /// Extension trait providing combinator methods for streams.
@@ -317,6 +323,24 @@ pub trait StreamExt: Stream {
         ReadyChunks::new(self, size)
     }
 
+    /// Splits this stream into `partitions` ordered sub-streams keyed by `key_fn`.
+    ///
+    /// See [`partition_by_key`] for the ordering, backpressure, and
+    /// cancellation contract.
+    fn partition_by_key<K, F>(
+        self,
+        key_fn: F,
+        partitions: usize,
+        per_partition_buffer: usize,
+    ) -> (PartitionRouter<Self, F>, Vec<KeyPartition<Self::Item>>)
+    where
+        Self: Sized + Unpin,
+        F: FnMut(&Self::Item) -> K,
+        K: Hash,
+    {
+        partition_by_key(self, key_fn, partitions, per_partition_buffer)
+    }
+
     /// Folds all items into a single value.
     fn fold<Acc, F>(self, init: Acc, f: F) -> Fold<Self, F, Acc>
     where
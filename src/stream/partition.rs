@@ -0,0 +1,702 @@
+//! Key-affinity fan-out of one stream into ordered partitions.
+//!
+//! [`partition_by_key`] splits a source stream into `N` [`KeyPartition`]
+//! sub-streams. Every item is routed by hashing the key returned from the
+//! key function, so equal keys always land in the same partition and arrive
+//! there in source order. This is the building block for per-entity
+//! sequential processing (per-account, per-session) with cross-entity
+//! parallelism.
+//!
+//! The [`PartitionRouter`] half owns the source and must be driven with
+//! [`PartitionRouter::run`]. Each partition has a bounded buffer. When the
+//! buffer for the next item's partition is full the router holds that item
+//! and stops pulling from the source: the slow partition never reorders the
+//! others, but it does eventually backpressure the source.
+//!
+//! Partition assignment is fixed for the lifetime of the adapter; there is no
+//! rebalancing.
+
+use super::{Stream, StreamExt};
+use crate::cx::Cx;
+use crate::runtime::yield_now;
+use crate::util::DetBuildHasher;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::hash::{BuildHasher, Hash};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+/// Cooperative budget for items routed in a single executor turn.
+///
+/// Without this bound, an always-ready source feeding partitions with spare
+/// capacity can monopolize a poll until the input is fully drained.
+const PARTITION_ROUTE_BUDGET: usize = 1024;
+
+/// Splits `stream` into `partitions` ordered sub-streams keyed by `key_fn`.
+///
+/// Returns the router, which must be driven with [`PartitionRouter::run`],
+/// and one [`KeyPartition`] per partition, indexed by partition number.
+///
+/// # Guarantees
+///
+/// - Items with equal keys are always routed to the same partition and are
+///   yielded by it in source order.
+/// - A partition whose buffer is full blocks the router on that item only;
+///   items already buffered for other partitions stay available. Once the
+///   router is blocked it stops pulling from the source.
+/// - On cancellation the router stops pulling, hands the item it was holding
+///   to its partition, and closes the partitions. Consumers still receive
+///   every buffered item before seeing `None`.
+/// - Dropping a [`KeyPartition`] discards its buffered and future items; the
+///   other partitions are unaffected.
+///
+/// Keys are hashed with a [`DetBuildHasher`] chosen once per adapter, so the
+/// mapping is stable for the adapter's lifetime and deterministic in lab
+/// builds.
+///
+/// # Panics
+///
+/// Panics if `partitions` or `per_partition_buffer` is zero.
+pub fn partition_by_key<S, K, F>(
+    stream: S,
+    key_fn: F,
+    partitions: usize,
+    per_partition_buffer: usize,
+) -> (PartitionRouter<S, F>, Vec<KeyPartition<S::Item>>)
+where
+    S: Stream + Unpin,
+    F: FnMut(&S::Item) -> K,
+    K: Hash,
+{
+    assert!(partitions > 0, "partition count must be non-zero");
+    assert!(
+        per_partition_buffer > 0,
+        "per-partition buffer must be non-zero"
+    );
+
+    let shared = Arc::new(Mutex::new(Shared {
+        partitions: (0..partitions)
+            .map(|_| PartitionSlot {
+                buffer: VecDeque::with_capacity(per_partition_buffer),
+                waker: None,
+                dropped: false,
+            })
+            .collect(),
+        capacity: per_partition_buffer,
+        source_done: false,
+        router_waker: None,
+        discarded: 0,
+    }));
+
+    let handles = (0..partitions)
+        .map(|index| KeyPartition {
+            shared: Arc::clone(&shared),
+            index,
+        })
+        .collect();
+
+    let router = PartitionRouter {
+        stream,
+        key_fn,
+        hasher: DetBuildHasher::default(),
+        partitions,
+        shared,
+    };
+
+    (router, handles)
+}
+
+struct PartitionSlot<T> {
+    buffer: VecDeque<T>,
+    waker: Option<Waker>,
+    dropped: bool,
+}
+
+struct Shared<T> {
+    partitions: Vec<PartitionSlot<T>>,
+    capacity: usize,
+    source_done: bool,
+    router_waker: Option<Waker>,
+    discarded: u64,
+}
+
+enum Offer {
+    Accepted,
+    Discarded,
+    Forced,
+}
+
+/// Summary of a completed [`PartitionRouter::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartitionReport {
+    /// Items routed into each partition, indexed by partition number.
+    pub routed: Vec<u64>,
+    /// Items discarded because their partition's consumer was dropped.
+    pub discarded: u64,
+    /// Items still buffered across all partitions when cancellation stopped
+    /// the router. Consumers drain these before observing end of stream.
+    pub buffered_at_cancel: u64,
+    /// Whether the router stopped because of cancellation rather than source
+    /// exhaustion.
+    pub cancelled: bool,
+}
+
+impl PartitionReport {
+    /// Returns the total number of items routed across all partitions.
+    #[must_use]
+    pub fn total_routed(&self) -> u64 {
+        self.routed.iter().sum()
+    }
+}
+
+/// The routing half of [`partition_by_key`].
+///
+/// Owns the source stream. Dropping the router without running it to
+/// completion closes every partition after its buffered items.
+#[must_use = "partitions receive nothing unless the router is run"]
+pub struct PartitionRouter<S: Stream, F> {
+    stream: S,
+    key_fn: F,
+    hasher: DetBuildHasher,
+    partitions: usize,
+    shared: Arc<Mutex<Shared<S::Item>>>,
+}
+
+impl<S: Stream, F> std::fmt::Debug for PartitionRouter<S, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PartitionRouter")
+            .field("partitions", &self.partitions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Stream + Unpin, F> PartitionRouter<S, F> {
+    /// Returns the number of partitions.
+    #[must_use]
+    pub const fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// Returns the partition that `key` routes to.
+    #[must_use]
+    pub fn partition_for<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        // The remainder is strictly less than the partition count.
+        #[allow(clippy::cast_possible_truncation)]
+        let index = (self.hasher.hash_one(key) % self.partitions as u64) as usize;
+        index
+    }
+
+    /// Routes the source stream into the partitions until it is exhausted or
+    /// `cx` is cancelled.
+    ///
+    /// The router checks `cx` before pulling each item. On cancellation it
+    /// stops pulling, enqueues any item it was holding (temporarily exceeding
+    /// that partition's buffer by one), and closes the partitions so their
+    /// consumers drain what remains.
+    pub async fn run<K>(mut self, cx: &Cx) -> PartitionReport
+    where
+        F: FnMut(&S::Item) -> K,
+        K: Hash,
+    {
+        let mut report = PartitionReport {
+            routed: vec![0; self.partitions()],
+            ..PartitionReport::default()
+        };
+        let mut routed_since_yield = 0usize;
+
+        loop {
+            if cx.checkpoint().is_err() {
+                report.cancelled = true;
+                break;
+            }
+            let Some(item) = self.stream.next().await else {
+                break;
+            };
+            let key = (self.key_fn)(&item);
+            let index = self.partition_for(&key);
+
+            let mut pending = Some(item);
+            let offer = poll_fn(|task| self.poll_offer(cx, index, &mut pending, task)).await;
+            match offer {
+                Offer::Accepted => report.routed[index] += 1,
+                Offer::Discarded => report.discarded += 1,
+                Offer::Forced => {
+                    report.routed[index] += 1;
+                    report.cancelled = true;
+                    break;
+                }
+            }
+
+            routed_since_yield += 1;
+            if routed_since_yield >= PARTITION_ROUTE_BUDGET {
+                routed_since_yield = 0;
+                yield_now().await;
+            }
+        }
+
+        let shared = self.shared.lock();
+        report.discarded += shared.discarded;
+        if report.cancelled {
+            report.buffered_at_cancel = shared
+                .partitions
+                .iter()
+                .map(|slot| slot.buffer.len() as u64)
+                .sum();
+        }
+        drop(shared);
+        report
+    }
+
+    fn poll_offer(
+        &self,
+        cx: &Cx,
+        index: usize,
+        pending: &mut Option<S::Item>,
+        task: &mut Context<'_>,
+    ) -> Poll<Offer> {
+        let mut shared = self.shared.lock();
+        let capacity = shared.capacity;
+        let slot = &mut shared.partitions[index];
+
+        if slot.dropped {
+            drop(shared);
+            pending.take();
+            return Poll::Ready(Offer::Discarded);
+        }
+
+        let offer = if slot.buffer.len() < capacity {
+            Offer::Accepted
+        } else if cx.is_cancel_requested() {
+            Offer::Forced
+        } else {
+            shared.router_waker = Some(task.waker().clone());
+            return Poll::Pending;
+        };
+
+        if let Some(item) = pending.take() {
+            slot.buffer.push_back(item);
+        }
+        let waker = slot.waker.take();
+        drop(shared);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Poll::Ready(offer)
+    }
+}
+
+impl<S: Stream, F> Drop for PartitionRouter<S, F> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock();
+        shared.source_done = true;
+        let wakers: Vec<Waker> = shared
+            .partitions
+            .iter_mut()
+            .filter_map(|slot| slot.waker.take())
+            .collect();
+        drop(shared);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// One ordered output of [`partition_by_key`].
+///
+/// Yields the items routed to this partition in source order, and `None`
+/// once the router has finished and the buffer is empty.
+#[must_use = "streams do nothing unless polled"]
+pub struct KeyPartition<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    index: usize,
+}
+
+impl<T> KeyPartition<T> {
+    /// Returns this partition's index.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the number of items currently buffered for this partition.
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.shared.lock().partitions[self.index].buffer.len()
+    }
+}
+
+impl<T> std::fmt::Debug for KeyPartition<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPartition")
+            .field("index", &self.index)
+            .field("buffered", &self.buffered())
+            .finish()
+    }
+}
+
+impl<T> Stream for KeyPartition<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, task: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut shared = self.shared.lock();
+        if let Some(item) = shared.partitions[self.index].buffer.pop_front() {
+            let waker = shared.router_waker.take();
+            drop(shared);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+            return Poll::Ready(Some(item));
+        }
+        if shared.source_done {
+            return Poll::Ready(None);
+        }
+        shared.partitions[self.index].waker = Some(task.waker().clone());
+        Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let shared = self.shared.lock();
+        let buffered = shared.partitions[self.index].buffer.len();
+        if shared.source_done {
+            (buffered, Some(buffered))
+        } else {
+            (buffered, None)
+        }
+    }
+}
+
+impl<T> Drop for KeyPartition<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock();
+        let slot = &mut shared.partitions[self.index];
+        slot.dropped = true;
+        slot.waker = None;
+        let discarded = std::mem::take(&mut slot.buffer);
+        shared.discarded += discarded.len() as u64;
+        let waker = shared.router_waker.take();
+        drop(shared);
+        drop(discarded);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::runtime::test as lab_test;
+    use crate::stream::iter;
+    use crate::types::Budget;
+    use crate::util::DetRng;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+    use std::future::Future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn poll_router<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        let waker = Waker::noop();
+        let mut task_cx = Context::from_waker(waker);
+        future.poll(&mut task_cx)
+    }
+
+    fn poll_partition<T>(partition: &mut KeyPartition<T>) -> Poll<Option<T>> {
+        let waker = Waker::noop();
+        let mut task_cx = Context::from_waker(waker);
+        Pin::new(partition).poll_next(&mut task_cx)
+    }
+
+    fn drain_ready<T>(partition: &mut KeyPartition<T>) -> Vec<T> {
+        let mut out = Vec::new();
+        while let Poll::Ready(Some(item)) = poll_partition(partition) {
+            out.push(item);
+        }
+        out
+    }
+
+    /// Routes `(key, seq)` items through a lab runtime where each consumer
+    /// yields a seed-dependent number of times between items, and returns
+    /// what every partition observed.
+    fn run_lab_partitioning(
+        seed: u64,
+        items: Vec<(u8, u32)>,
+        partitions: usize,
+        buffer: usize,
+    ) -> (PartitionReport, Vec<Vec<(u8, u32)>>) {
+        lab_test(seed, |lab| {
+            let root = lab.state.create_root_region(Budget::INFINITE);
+            let (router, handles) = iter(items).partition_by_key(|item| item.0, partitions, buffer);
+            let report = Arc::new(Mutex::new(None));
+            let observed: Arc<Mutex<Vec<Vec<(u8, u32)>>>> =
+                Arc::new(Mutex::new(vec![Vec::new(); partitions]));
+
+            let report_slot = Arc::clone(&report);
+            let (router_task, _) = lab
+                .state
+                .create_task(root, Budget::INFINITE, async move {
+                    let cx = Cx::for_testing();
+                    *report_slot.lock() = Some(router.run(&cx).await);
+                })
+                .expect("spawn router");
+            let mut tasks = vec![router_task];
+
+            for mut handle in handles {
+                let observed = Arc::clone(&observed);
+                let mut rng = DetRng::new(seed ^ (handle.index() as u64 + 1));
+                let (task, _) = lab
+                    .state
+                    .create_task(root, Budget::INFINITE, async move {
+                        while let Some(item) = handle.next().await {
+                            observed.lock()[handle.index()].push(item);
+                            for _ in 0..rng.next_usize(3) {
+                                yield_now().await;
+                            }
+                        }
+                    })
+                    .expect("spawn consumer");
+                tasks.push(task);
+            }
+
+            for task in tasks {
+                lab.scheduler.lock().schedule(task, 0);
+            }
+            lab.run_until_quiescent();
+
+            let report = report.lock().take().expect("router completed");
+            let observed = observed.lock().clone();
+            (report, observed)
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn per_key_order_preserved_across_lab_interleavings(
+            seed in any::<u64>(),
+            keys in prop::collection::vec(0u8..16, 1..200),
+            partitions in 1usize..6,
+            buffer in 1usize..4,
+        ) {
+            let items: Vec<(u8, u32)> = keys
+                .iter()
+                .enumerate()
+                .map(|(seq, &key)| (key, seq as u32))
+                .collect();
+            let (report, observed) = run_lab_partitioning(seed, items.clone(), partitions, buffer);
+
+            prop_assert!(!report.cancelled);
+            prop_assert_eq!(report.total_routed(), items.len() as u64);
+
+            let mut partition_of_key = BTreeMap::new();
+            let mut per_key: BTreeMap<u8, Vec<u32>> = BTreeMap::new();
+            for (index, seen) in observed.iter().enumerate() {
+                prop_assert_eq!(report.routed[index], seen.len() as u64);
+                for &(key, seq) in seen {
+                    let first = *partition_of_key.entry(key).or_insert(index);
+                    prop_assert_eq!(first, index, "key {} split across partitions", key);
+                    per_key.entry(key).or_default().push(seq);
+                }
+            }
+
+            let mut expected: BTreeMap<u8, Vec<u32>> = BTreeMap::new();
+            for &(key, seq) in &items {
+                expected.entry(key).or_default().push(seq);
+            }
+            prop_assert_eq!(per_key, expected);
+        }
+    }
+
+    #[test]
+    fn slow_partition_backpressures_source_without_reordering_others() {
+        init_test("slow_partition_backpressures_source_without_reordering_others");
+        let cx: Cx = Cx::for_testing();
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let pulled_in_source = Arc::clone(&pulled);
+        let source = iter(0u64..64).inspect(move |_| {
+            pulled_in_source.fetch_add(1, Ordering::SeqCst);
+        });
+        let (router, mut handles) = source.partition_by_key(|item| *item, 2, 2);
+        let slow_index = router.partition_for(&0u64);
+        let fast_index = 1 - slow_index;
+        let slow_items: Vec<u64> = (0u64..64)
+            .filter(|item| router.partition_for(item) == slow_index)
+            .collect();
+        let fast_items: Vec<u64> = (0u64..64)
+            .filter(|item| router.partition_for(item) == fast_index)
+            .collect();
+        assert!(slow_items.len() > 3, "fixture needs a busy slow partition");
+
+        let mut run = std::pin::pin!(router.run(&cx));
+        let mut fast_seen = Vec::new();
+        for _ in 0..64 {
+            if poll_router(run.as_mut()).is_ready() {
+                break;
+            }
+            fast_seen.extend(drain_ready(&mut handles[fast_index]));
+        }
+
+        // The slow partition is full and the router holds its next item, so
+        // nothing past that item has been pulled from the source.
+        let held = slow_items[2];
+        crate::assert_with_log!(
+            handles[slow_index].buffered() == 2,
+            "slow partition at capacity",
+            2,
+            handles[slow_index].buffered()
+        );
+        crate::assert_with_log!(
+            pulled.load(Ordering::SeqCst) == held as usize + 1,
+            "source stops at the held item",
+            held + 1,
+            pulled.load(Ordering::SeqCst)
+        );
+        let expected_fast: Vec<u64> = fast_items.iter().copied().filter(|&i| i < held).collect();
+        crate::assert_with_log!(
+            fast_seen == expected_fast,
+            "fast partition unaffected",
+            expected_fast,
+            fast_seen
+        );
+
+        // Draining the slow partition releases the source.
+        let mut slow_seen = Vec::new();
+        let report = loop {
+            slow_seen.extend(drain_ready(&mut handles[slow_index]));
+            fast_seen.extend(drain_ready(&mut handles[fast_index]));
+            if let Poll::Ready(report) = poll_router(run.as_mut()) {
+                break report;
+            }
+        };
+        slow_seen.extend(drain_ready(&mut handles[slow_index]));
+        fast_seen.extend(drain_ready(&mut handles[fast_index]));
+
+        assert_eq!(slow_seen, slow_items);
+        assert_eq!(fast_seen, fast_items);
+        assert_eq!(report.total_routed(), 64);
+        assert!(poll_partition(&mut handles[slow_index]).is_ready());
+        crate::test_complete!("slow_partition_backpressures_source_without_reordering_others");
+    }
+
+    #[test]
+    fn cancellation_drains_buffered_items_in_every_partition() {
+        init_test("cancellation_drains_buffered_items_in_every_partition");
+        let cx: Cx = Cx::for_testing();
+        let (router, mut handles) = iter(0u32..100).partition_by_key(|item| *item, 4, 3);
+        let mut run = std::pin::pin!(router.run(&cx));
+
+        // Nobody consumes, so the router blocks once some partition fills.
+        assert!(poll_router(run.as_mut()).is_pending());
+        let buffered_before: usize = handles.iter().map(KeyPartition::buffered).sum();
+
+        cx.set_cancel_requested(true);
+        let report = match poll_router(run.as_mut()) {
+            Poll::Ready(report) => report,
+            Poll::Pending => panic!("router must finish once cancelled"),
+        };
+        crate::assert_with_log!(report.cancelled, "report cancelled", true, report.cancelled);
+        // The held item is handed over on cancel.
+        assert_eq!(report.buffered_at_cancel as usize, buffered_before + 1);
+        assert_eq!(report.total_routed(), report.buffered_at_cancel);
+
+        let mut drained = 0u64;
+        for (index, handle) in handles.iter_mut().enumerate() {
+            let items = drain_ready(handle);
+            assert_eq!(items.len() as u64, report.routed[index]);
+            assert!(items.windows(2).all(|pair| pair[0] < pair[1]));
+            drained += items.len() as u64;
+            assert!(matches!(poll_partition(handle), Poll::Ready(None)));
+        }
+        crate::assert_with_log!(
+            drained == report.buffered_at_cancel,
+            "every buffered item drained",
+            report.buffered_at_cancel,
+            drained
+        );
+        crate::test_complete!("cancellation_drains_buffered_items_in_every_partition");
+    }
+
+    #[test]
+    fn dropped_partition_discards_without_blocking_others() {
+        init_test("dropped_partition_discards_without_blocking_others");
+        let cx: Cx = Cx::for_testing();
+        let (router, mut handles) = iter(0u32..50).partition_by_key(|item| *item, 2, 1);
+        let dropped_index = router.partition_for(&0u32);
+        let kept_index = 1 - dropped_index;
+        drop(handles.remove(dropped_index));
+        let mut kept = handles.pop().expect("kept partition");
+        assert_eq!(kept.index(), kept_index);
+
+        let mut run = std::pin::pin!(router.run(&cx));
+        let mut seen = Vec::new();
+        let report = loop {
+            seen.extend(drain_ready(&mut kept));
+            if let Poll::Ready(report) = poll_router(run.as_mut()) {
+                break report;
+            }
+        };
+        seen.extend(drain_ready(&mut kept));
+
+        assert_eq!(report.routed[kept_index], seen.len() as u64);
+        assert_eq!(report.routed[dropped_index], 0);
+        assert_eq!(report.discarded + seen.len() as u64, 50);
+        crate::test_complete!("dropped_partition_discards_without_blocking_others");
+    }
+
+    fn assert_balanced(counts: &[usize], total: usize, label: &str) {
+        let expected = total / counts.len();
+        for (index, &count) in counts.iter().enumerate() {
+            let deviation = count.abs_diff(expected);
+            assert!(
+                deviation * 10 <= expected,
+                "{label}: partition {index} got {count}, expected ~{expected} ({counts:?})"
+            );
+        }
+    }
+
+    #[test]
+    fn hash_distribution_is_balanced_for_common_key_types() {
+        init_test("hash_distribution_is_balanced_for_common_key_types");
+        let (router, _handles) = iter(Vec::<u64>::new()).partition_by_key(|item| *item, 8, 1);
+        let total = 16_384usize;
+
+        let mut counts = vec![0usize; 8];
+        for key in 0..total as u64 {
+            counts[router.partition_for(&key)] += 1;
+        }
+        assert_balanced(&counts, total, "sequential u64");
+
+        let mut counts = vec![0usize; 8];
+        for key in 0..total {
+            counts[router.partition_for(&format!("session-{key}"))] += 1;
+        }
+        assert_balanced(&counts, total, "string");
+
+        let mut counts = vec![0usize; 8];
+        for key in 0..total as u32 {
+            counts[router.partition_for(&(key / 128, key % 128))] += 1;
+        }
+        assert_balanced(&counts, total, "tuple");
+
+        let mut counts = vec![0usize; 8];
+        for key in 0..total as u64 {
+            counts[router.partition_for(&(key << 32))] += 1;
+        }
+        assert_balanced(&counts, total, "high-bit u64");
+        crate::test_complete!("hash_distribution_is_balanced_for_common_key_types");
+    }
+}
@@ -46,6 +46,16 @@ use std::time::Duration;
 use crate::runtime::{RegionCreateError, RuntimeState, SpawnError};
 use crate::types::{Budget, CancelReason, Outcome, RegionId, TaskId, Time};
 
+#[path = "supervision_tree.rs"]
+mod tree;
+
+pub(crate) use tree::{ChildRegistration, SupervisedActorNode};
+pub use tree::{
+    ChildRunState, ChildSnapshot, ChildSpecKind, DEFAULT_RESTART_HISTORY_LIMIT,
+    RestartIntensitySnapshot, SUPERVISION_TREE_SCHEMA_VERSION, SupervisionTree,
+    SupervisionTreeSnapshot, SupervisorKind, SupervisorSnapshot, TreeExportFormat, export_tree,
+};
+
 // ============================================================================
 // ChildName — reference-counted name for zero-cost cloning on hot paths
 // ============================================================================
@@ -203,6 +213,10 @@ pub enum SupervisionStrategy {
     ///
     /// Restarts are rate-limited by a sliding window. If the restart
     /// limit is exceeded, the strategy escalates to [`SupervisionStrategy::Stop`].
+    ///
+    /// In a `panic = "abort"` build a panicking actor takes the process down
+    /// before any restart can happen; see
+    /// [`PanicStrategy`](crate::runtime::PanicStrategy).
     Restart(RestartConfig),
 
     /// Escalate the failure to the parent region.
@@ -423,6 +437,25 @@ pub enum EscalationPolicy {
     ResetCounter,
 }
 
+/// What a supervisor does when a child detects a poisoned lock.
+///
+/// A lock shared by a supervisor's children is poisoned when one of them
+/// panics while holding it (see [`PoisonError`](crate::sync::PoisonError)).
+/// The panicking child's own failure is handled by its
+/// [`SupervisionStrategy`]; this policy covers the siblings, which may go on
+/// to observe the half-updated value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum PoisonPolicy {
+    /// Leave the poison to the acquiring child, which sees a poisoned-lock
+    /// error and may repair the value itself.
+    #[default]
+    Surface,
+
+    /// Cancel every child of the supervisor and restart the restartable
+    /// ones, rebuilding the shared state from scratch.
+    RestartSubtree,
+}
+
 /// Full configuration for supervisor behavior.
 ///
 /// Combines restart policy, rate limiting, backoff, and escalation.
@@ -883,6 +916,7 @@ pub struct SupervisorBuilder {
     budget: Option<Budget>,
     tie_break: StartTieBreak,
     restart_policy: RestartPolicy,
+    poison_policy: PoisonPolicy,
     children: Vec<ChildSpec>,
 }
 
@@ -895,6 +929,7 @@ impl SupervisorBuilder {
             budget: None,
             tie_break: StartTieBreak::InsertionOrder,
             restart_policy: RestartPolicy::OneForOne,
+            poison_policy: PoisonPolicy::Surface,
             children: Vec::new(),
         }
     }
@@ -924,6 +959,15 @@ impl SupervisorBuilder {
         self
     }
 
+    /// Set what happens when a child detects a poisoned lock.
+    ///
+    /// See [`CompiledSupervisor::restart_plan_for_poison`].
+    #[must_use]
+    pub fn with_poison_policy(mut self, poison_policy: PoisonPolicy) -> Self {
+        self.poison_policy = poison_policy;
+        self
+    }
+
     /// Add a child spec.
     #[must_use]
     pub fn child(mut self, child: ChildSpec) -> Self {
@@ -941,6 +985,7 @@ impl SupervisorBuilder {
             && self.budget == other.budget
             && self.tie_break == other.tie_break
             && self.restart_policy == other.restart_policy
+            && self.poison_policy == other.poison_policy
             && self.children.len() == other.children.len()
             && self
                 .children
@@ -960,6 +1005,7 @@ impl SupervisorBuilder {
         hash_budget_option(&mut hasher, self.budget);
         hash_start_tie_break(&mut hasher, self.tie_break);
         hash_restart_policy(&mut hasher, self.restart_policy);
+        hash_poison_policy(&mut hasher, self.poison_policy);
         hasher.write_u64(self.children.len() as u64);
         for child in &self.children {
             hash_child_spec_fields(child, &mut hasher);
@@ -1092,6 +1138,13 @@ fn hash_restart_policy(hasher: &mut crate::util::DetHasher, policy: RestartPolic
     }
 }
 
+fn hash_poison_policy(hasher: &mut crate::util::DetHasher, policy: PoisonPolicy) {
+    match policy {
+        PoisonPolicy::Surface => hasher.write_u8(0),
+        PoisonPolicy::RestartSubtree => hasher.write_u8(1),
+    }
+}
+
 fn hash_start_tie_break(hasher: &mut crate::util::DetHasher, tie_break: StartTieBreak) {
     match tie_break {
         StartTieBreak::InsertionOrder => hasher.write_u8(0),
@@ -1110,6 +1163,8 @@ pub struct CompiledSupervisor {
     pub tie_break: StartTieBreak,
     /// Restart policy applied when a child fails.
     pub restart_policy: RestartPolicy,
+    /// What happens when a child detects a poisoned lock.
+    pub poison_policy: PoisonPolicy,
     /// Child specifications (including start factories).
     pub children: Vec<ChildSpec>,
     /// Deterministic start order as indices into `children`.
@@ -1283,6 +1338,7 @@ impl CompiledSupervisor {
             budget: builder.budget,
             tie_break: builder.tie_break,
             restart_policy: builder.restart_policy,
+            poison_policy: builder.poison_policy,
             children: builder.children,
             start_order: order,
         })
@@ -1403,12 +1459,43 @@ impl CompiledSupervisor {
         }
     }
 
+    /// Compute the restart plan after `detected_by` finds a lock shared by
+    /// this supervisor's children poisoned.
+    ///
+    /// Under [`PoisonPolicy::RestartSubtree`] the plan covers every child, as
+    /// for a [`RestartPolicy::OneForAll`] failure, whatever the supervisor's
+    /// own restart policy: any sibling may have read the half-updated value.
+    /// `restart_order` is pruned exactly as in
+    /// [`restart_plan_for_failure`](Self::restart_plan_for_failure). Under
+    /// [`PoisonPolicy::Surface`] this returns `None`.
+    #[must_use]
+    pub fn restart_plan_for_poison(&self, detected_by: &str) -> Option<SupervisorRestartPlan> {
+        if self.poison_policy == PoisonPolicy::Surface {
+            return None;
+        }
+        let detected_idx = self
+            .children
+            .iter()
+            .position(|child| child.name == detected_by)?;
+        let affected_positions = self.affected_positions(detected_idx, RestartPolicy::OneForAll)?;
+        Some(self.pruned_restart_plan(&affected_positions, RestartPolicy::OneForAll))
+    }
+
     #[must_use]
     fn affected_positions_for_idx(&self, failed_child_idx: usize) -> Option<Vec<usize>> {
+        self.affected_positions(failed_child_idx, self.restart_policy)
+    }
+
+    #[must_use]
+    fn affected_positions(
+        &self,
+        failed_child_idx: usize,
+        policy: RestartPolicy,
+    ) -> Option<Vec<usize>> {
         let failed_pos = self.start_pos_for_child_idx(failed_child_idx)?;
 
         let total = self.start_order.len();
-        let affected_positions = match self.restart_policy {
+        let affected_positions = match policy {
             RestartPolicy::OneForOne => vec![failed_pos],
             RestartPolicy::OneForAll => (0..total).collect(),
             RestartPolicy::RestForOne => (failed_pos..total).collect(),
@@ -1430,7 +1517,16 @@ impl CompiledSupervisor {
         failed_child_idx: usize,
     ) -> Option<SupervisorRestartPlan> {
         let affected_positions = self.affected_positions_for_idx(failed_child_idx)?;
+        Some(self.pruned_restart_plan(&affected_positions, self.restart_policy))
+    }
 
+    /// Cancels `affected_positions` and restarts the restartable children
+    /// among them whose affected dependencies are also restarted.
+    fn pruned_restart_plan(
+        &self,
+        affected_positions: &[usize],
+        policy: RestartPolicy,
+    ) -> SupervisorRestartPlan {
         let mut cancel_order = Vec::with_capacity(affected_positions.len());
         for &pos in affected_positions.iter().rev() {
             cancel_order.push(self.children[self.start_order[pos]].name.clone());
@@ -1443,13 +1539,13 @@ impl CompiledSupervisor {
             .map(|(idx, child)| (child.name.as_str(), idx))
             .collect::<std::collections::HashMap<_, _>>();
         let mut affected_children = vec![false; self.children.len()];
-        for &pos in &affected_positions {
+        for &pos in affected_positions {
             affected_children[self.start_order[pos]] = true;
         }
 
         let mut scheduled_restart = vec![false; self.children.len()];
         let mut restart_order = Vec::with_capacity(affected_positions.len());
-        for &pos in &affected_positions {
+        for &pos in affected_positions {
             let child_idx = self.start_order[pos];
             let child = &self.children[child_idx];
 
@@ -1471,11 +1567,11 @@ impl CompiledSupervisor {
             restart_order.push(child.name.clone());
         }
 
-        Some(SupervisorRestartPlan {
-            policy: self.restart_policy,
+        SupervisorRestartPlan {
+            policy,
             cancel_order,
             restart_order,
-        })
+        }
     }
 
     #[must_use]
@@ -1618,6 +1714,11 @@ impl CompiledSupervisor {
     /// live restart-on-failure — a child crash under a `CompiledSupervisor` tree is NOT restarted
     /// at runtime (asupersync-u2vgjg).
     ///
+    /// When `cx` belongs to a live task, that task becomes the supervising task: it traps exits
+    /// and is linked to every started child, so child terminations arrive as trapped
+    /// [`ExitSignal`](crate::link::ExitSignal)s that
+    /// [`SupervisorHandle::next_exit_decision`] feeds to [`Supervisor::on_exit_signal`].
+    ///
     /// # Why this keeps `&mut RuntimeState` (br-asupersync-c6uw5y)
     ///
     /// Supervisor boot is deliberately **not** routed through the v2 spawn
@@ -1654,6 +1755,7 @@ impl CompiledSupervisor {
             Started,
             Failed(SpawnError),
             DependencyUnavailable {
+                dependency: ChildName,
                 dependency_error: Option<SpawnError>,
             },
         }
@@ -1675,6 +1777,7 @@ impl CompiledSupervisor {
             .map(|(idx, child)| (child.name.clone(), idx))
             .collect::<std::collections::HashMap<_, _>>();
         let mut boot_states = vec![BootState::NotStarted; self.children.len()];
+        let mut task_ids = vec![None; self.children.len()];
         let mut started = Vec::new();
         for &idx in &self.start_order {
             let (child_name, child_required, child_dependencies, start_immediately) = {
@@ -1699,7 +1802,7 @@ impl CompiledSupervisor {
                 match &boot_states[dep_idx] {
                     BootState::Started => None,
                     BootState::Failed(err) => Some((dependency.clone(), Some(err.clone()))),
-                    BootState::DependencyUnavailable { dependency_error } => {
+                    BootState::DependencyUnavailable { dependency_error, .. } => {
                         Some((dependency.clone(), dependency_error.clone()))
                     }
                     BootState::NotStarted | BootState::Deferred => Some((dependency.clone(), None)),
@@ -1717,16 +1820,22 @@ impl CompiledSupervisor {
                         region,
                     });
                 }
-                boot_states[idx] = BootState::DependencyUnavailable { dependency_error };
+                boot_states[idx] = BootState::DependencyUnavailable {
+                    dependency,
+                    dependency_error,
+                };
                 continue;
             }
 
             let child = &mut self.children[idx];
             match child.start.start(&scope, state, cx) {
-                Ok(task_id) => started.push(StartedChild {
-                    name: child_name.clone(),
-                    task_id,
-                }),
+                Ok(task_id) => {
+                    task_ids[idx] = Some(task_id);
+                    started.push(StartedChild {
+                        name: child_name.clone(),
+                        task_id,
+                    });
+                }
                 Err(err) => {
                     boot_states[idx] = BootState::Failed(err.clone());
                     cx.trace("supervisor_child_start_failed");
@@ -1748,14 +1857,88 @@ impl CompiledSupervisor {
             }
         }
 
+        let tree_children = self
+            .start_order
+            .iter()
+            .map(|&idx| {
+                let child = &self.children[idx];
+                let (child_state, last_outcome) = match &boot_states[idx] {
+                    BootState::Started => (ChildRunState::Running, None),
+                    BootState::NotStarted => (ChildRunState::Starting, None),
+                    BootState::Deferred => (ChildRunState::Stopped, Some("deferred".to_string())),
+                    BootState::Failed(err) => {
+                        (ChildRunState::Stopped, Some(format!("start failed: {err}")))
+                    }
+                    BootState::DependencyUnavailable { dependency, .. } => (
+                        ChildRunState::Stopped,
+                        Some(format!("dependency {dependency} unavailable")),
+                    ),
+                };
+                ChildRegistration {
+                    name: child.name.clone(),
+                    spec: ChildSpecKind::Task,
+                    restart: child.restart.clone(),
+                    task: task_ids[idx],
+                    state: child_state,
+                    last_outcome,
+                }
+            })
+            .collect();
+        state.supervision_tree().register_supervisor(
+            state,
+            self.name.clone(),
+            SupervisorKind::Tree,
+            region,
+            self.restart_policy,
+            None,
+            tree_children,
+        );
+        let exits = trap_child_exits(state, cx, &started);
+
         Ok(SupervisorHandle {
             name: self.name,
             region,
             started,
+            parent_region,
+            exits,
         })
     }
 }
 
+/// Links the supervising task behind `cx` to each started child and traps
+/// their exits into a channel.
+///
+/// Returns `None` when `cx` does not belong to a live task of `state`,
+/// leaving nothing to link.
+fn trap_child_exits(
+    state: &mut RuntimeState,
+    cx: &crate::cx::Cx,
+    started: &[StartedChild],
+) -> Option<crate::channel::mpsc::Receiver<crate::link::ExitSignal>> {
+    let supervisor_task = cx.task_id();
+    // Compare contexts, not ids: a detached test `Cx` carries a placeholder
+    // id that may name an unrelated task.
+    let is_live_task = state
+        .task(supervisor_task)
+        .and_then(|record| record.cx_inner.as_ref())
+        .is_some_and(|inner| Arc::ptr_eq(inner, &cx.inner));
+    if started.is_empty() || !is_live_task {
+        return None;
+    }
+    // Each linked child exits once, so the mailbox never has to refuse one.
+    let (tx, rx) = crate::channel::mpsc::channel(started.len());
+    cx.trap_exits(true);
+    let _ = state.set_exit_mailbox(supervisor_task, crate::link::ExitMailbox::from_sender(tx));
+    for child in started {
+        // Children started by this boot have not been polled yet, so linking
+        // owes no exit at once.
+        if let Ok((_, owed)) = state.link_tasks(supervisor_task, child.task_id) {
+            debug_assert!(owed.is_empty(), "freshly started child already exited");
+        }
+    }
+    Some(rx)
+}
+
 /// Result of spawning a compiled supervisor.
 #[derive(Debug)]
 pub struct SupervisorHandle {
@@ -1765,6 +1948,74 @@ pub struct SupervisorHandle {
     pub region: RegionId,
     /// Children that were started immediately (in start order).
     pub started: Vec<StartedChild>,
+    /// Region the supervisor was spawned under, the escalation target.
+    parent_region: RegionId,
+    /// Trapped exit signals from the started children.
+    exits: Option<crate::channel::mpsc::Receiver<crate::link::ExitSignal>>,
+}
+
+impl SupervisorHandle {
+    /// Returns `true` if child terminations reach this handle as trapped
+    /// exit signals, i.e. the supervisor was spawned from a live task.
+    #[must_use]
+    pub fn traps_child_exits(&self) -> bool {
+        self.exits.is_some()
+    }
+
+    /// Waits for the next trapped child exit and returns it with the
+    /// decision `supervisor` makes for it through
+    /// [`Supervisor::on_exit_signal`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvError::Disconnected`](crate::channel::mpsc::RecvError::Disconnected)
+    /// if child exits are not trapped or the supervising task has completed,
+    /// and [`RecvError::Cancelled`](crate::channel::mpsc::RecvError::Cancelled)
+    /// if `cx` is cancelled while waiting.
+    pub async fn next_exit_decision(
+        &mut self,
+        cx: &crate::cx::Cx,
+        supervisor: &mut Supervisor,
+        now: u64,
+        budget: Option<&mut Budget>,
+    ) -> Result<
+        (crate::link::ExitSignal, Option<SupervisionDecision>),
+        crate::channel::mpsc::RecvError,
+    > {
+        let Some(exits) = self.exits.as_mut() else {
+            return Err(crate::channel::mpsc::RecvError::Disconnected);
+        };
+        let signal = exits.recv(cx).await?;
+        let decision =
+            supervisor.on_exit_signal(&signal, self.region, Some(self.parent_region), now, budget);
+        Ok((signal, decision))
+    }
+
+    /// Like [`next_exit_decision`](Self::next_exit_decision) without
+    /// waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvError::Empty`](crate::channel::mpsc::RecvError::Empty)
+    /// if no child exit is queued, and the errors of `next_exit_decision`
+    /// otherwise.
+    pub fn try_next_exit_decision(
+        &mut self,
+        supervisor: &mut Supervisor,
+        now: u64,
+        budget: Option<&mut Budget>,
+    ) -> Result<
+        (crate::link::ExitSignal, Option<SupervisionDecision>),
+        crate::channel::mpsc::RecvError,
+    > {
+        let Some(exits) = self.exits.as_mut() else {
+            return Err(crate::channel::mpsc::RecvError::Disconnected);
+        };
+        let signal = exits.try_recv()?;
+        let decision =
+            supervisor.on_exit_signal(&signal, self.region, Some(self.parent_region), now, budget);
+        Ok((signal, decision))
+    }
 }
 
 /// Information about a child started by a supervisor.
@@ -3807,6 +4058,43 @@ impl Supervisor {
         decision
     }
 
+    /// Decide what to do when a trapped exit signal from a linked child
+    /// arrives.
+    ///
+    /// A supervising task links to its children and traps their exits (see
+    /// [`crate::link`]), so the runtime delivers each child termination as an
+    /// [`ExitSignal`](crate::link::ExitSignal) in its exit mailbox rather
+    /// than through separate per-child tracking.
+    /// [`SupervisorHandle::next_exit_decision`] drains that mailbox into this
+    /// method. Normal exits need no decision and return `None`; other reasons
+    /// map back to the [`Outcome`] that [`on_failure_with_budget`](Self::on_failure_with_budget)
+    /// judges.
+    pub fn on_exit_signal(
+        &mut self,
+        signal: &crate::link::ExitSignal,
+        region_id: RegionId,
+        parent_region_id: Option<RegionId>,
+        now: u64,
+        budget: Option<&mut Budget>,
+    ) -> Option<SupervisionDecision> {
+        use crate::monitor::DownReason;
+
+        let outcome = match &signal.reason {
+            DownReason::Normal => return None,
+            DownReason::Error(_) => Outcome::Err(()),
+            DownReason::Cancelled(reason) => Outcome::Cancelled(reason.clone()),
+            DownReason::Panicked(payload) => Outcome::Panicked(payload.clone()),
+        };
+        Some(self.on_failure_with_budget(
+            signal.from,
+            region_id,
+            parent_region_id,
+            &outcome,
+            now,
+            budget,
+        ))
+    }
+
     /// Get the restart history (if using Restart strategy).
     #[must_use]
     pub fn history(&self) -> Option<&RestartHistory> {
@@ -4272,6 +4560,85 @@ mod tests {
         }
     }
 
+    #[test]
+    fn trapped_link_exits_drive_supervision_decisions() {
+        use crate::error::{Error, ErrorKind};
+
+        init_test("trapped_link_exits_drive_supervision_decisions");
+
+        let log: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let mk = |name: &'static str| {
+            ChildSpec::new(
+                name,
+                LoggingStart {
+                    name,
+                    log: Arc::clone(&log),
+                },
+            )
+        };
+        let compiled = SupervisorBuilder::new("sup")
+            .child(mk("crashing"))
+            .child(mk("panicking"))
+            .child(mk("finishing"))
+            .compile()
+            .expect("compile");
+
+        let mut state = RuntimeState::new();
+        let parent = state.create_root_region(Budget::INFINITE);
+        let (supervisor_task, _handle) = state
+            .create_task(parent, Budget::INFINITE, async {})
+            .expect("supervising task");
+        let cx = state
+            .task(supervisor_task)
+            .and_then(|record| record.cx.clone())
+            .expect("task cx");
+
+        let mut handle = compiled
+            .spawn(&mut state, &cx, parent, Budget::INFINITE)
+            .expect("spawn");
+        assert!(handle.traps_child_exits());
+        assert!(cx.traps_exits(), "the supervising task traps child exits");
+
+        let children: Vec<TaskId> = handle.started.iter().map(|child| child.task_id).collect();
+        let outcomes = [
+            Outcome::Err(Error::new(ErrorKind::Internal)),
+            Outcome::Panicked(PanicPayload::new("bad")),
+            Outcome::Ok(()),
+        ];
+        for (child, outcome) in children.iter().zip(outcomes) {
+            assert!(state.complete_task(*child, outcome));
+            let (_waiters, observer) = state.task_completed(*child).into_parts();
+            observer.dispatch();
+        }
+        assert!(
+            state.take_deferred_cancel_dispatches().is_empty(),
+            "a trapping supervisor is never cancelled by a child exit"
+        );
+
+        let config = RestartConfig::new(3, Duration::from_mins(1));
+        let mut supervisor = Supervisor::new(SupervisionStrategy::Restart(config));
+        let mut decisions = Vec::new();
+        while let Ok((signal, decision)) = handle.try_next_exit_decision(&mut supervisor, 0, None) {
+            decisions.push((signal.from, decision));
+        }
+
+        assert_eq!(decisions.len(), 3);
+        assert_eq!(decisions[0].0, children[0]);
+        assert!(matches!(
+            decisions[0].1,
+            Some(SupervisionDecision::Restart { attempt: 1, .. })
+        ));
+        assert_eq!(decisions[1].0, children[1]);
+        assert!(matches!(
+            decisions[1].1,
+            Some(SupervisionDecision::Stop { .. })
+        ));
+        assert_eq!(decisions[2].0, children[2]);
+        assert!(decisions[2].1.is_none());
+
+        crate::test_complete!("trapped_link_exits_drive_supervision_decisions");
+    }
+
     #[test]
     fn stop_strategy_always_stops() {
         init_test("stop_strategy_always_stops");
@@ -5275,6 +5642,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn poison_policy_restarts_whole_subtree_on_poison_detection() {
+        init_test("poison_policy_restarts_whole_subtree_on_poison_detection");
+
+        let restart = || SupervisionStrategy::Restart(RestartConfig::default());
+        let builder = || {
+            SupervisorBuilder::new("sup")
+                .with_restart_policy(RestartPolicy::OneForOne)
+                .child(ChildSpec::new("ledger", noop_start).with_restart(restart()))
+                .child(
+                    ChildSpec::new("writer", noop_start)
+                        .depends_on("ledger")
+                        .with_restart(restart()),
+                )
+                .child(ChildSpec::new("audit", noop_start).depends_on("ledger"))
+        };
+
+        let surfaced = builder().compile().expect("compile");
+        assert_eq!(surfaced.poison_policy, PoisonPolicy::Surface);
+        assert!(surfaced.restart_plan_for_poison("writer").is_none());
+
+        let restarting = builder().with_poison_policy(PoisonPolicy::RestartSubtree);
+        assert!(!restarting.spec_eq(&builder()));
+        assert_ne!(restarting.spec_fingerprint(), builder().spec_fingerprint());
+
+        let compiled = restarting.compile().expect("compile");
+        let plan = compiled
+            .restart_plan_for_poison("writer")
+            .expect("poison restarts the subtree");
+        assert_eq!(plan.policy, RestartPolicy::OneForAll);
+        assert_eq!(plan.cancel_order, ["audit", "writer", "ledger"]);
+        // `audit` is cancelled with its siblings but keeps its Stop strategy.
+        assert_eq!(plan.restart_order, ["ledger", "writer"]);
+        assert!(compiled.restart_plan_for_poison("zzz").is_none());
+
+        crate::test_complete!("poison_policy_restarts_whole_subtree_on_poison_detection");
+    }
+
     #[test]
     fn restart_policy_equality() {
         init_test("restart_policy_equality");
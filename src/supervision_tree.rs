@@ -0,0 +1,944 @@
+//! Live supervision tree registry and DOT/JSON export.
+//!
+//! Every supervisor spawned through [`CompiledSupervisor::spawn`] and every
+//! actor spawned with `Scope::spawn_supervised_actor` registers a node in the
+//! runtime's [`SupervisionTree`]. Child state transitions (start, failure,
+//! restart) are applied to the registry under a single lock, so a
+//! [`SupervisionTreeSnapshot`] always reflects one instant: a child is never
+//! exported as running with the restart count of its previous incarnation.
+//!
+//! Snapshots are taken against the [`RuntimeState`], like
+//! [`RuntimeState::snapshot`]. Regions are reported with the same
+//! [`IdSnapshot`] identifiers as the region snapshot so the two views can be
+//! joined, and supervisors whose region has closed are dropped from the tree.
+//!
+//! # Ordering
+//!
+//! Supervisors are ordered by registration (parents always precede their
+//! nested supervisors) and children keep their start order, so two exports
+//! of the same tree diff line by line.
+//!
+//! [`CompiledSupervisor::spawn`]: super::CompiledSupervisor::spawn
+
+use super::{ChildName, RestartConfig, RestartPolicy, SupervisionStrategy};
+use crate::runtime::RuntimeState;
+use crate::runtime::state::IdSnapshot;
+use crate::types::{RegionId, TaskId, Time};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+/// Version of the JSON layout produced by [`SupervisionTreeSnapshot::to_json`].
+pub const SUPERVISION_TREE_SCHEMA_VERSION: u32 = 1;
+
+/// Default number of restart timestamps kept per child.
+pub const DEFAULT_RESTART_HISTORY_LIMIT: usize = 8;
+
+/// Output format for [`export_tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeExportFormat {
+    /// Graphviz DOT digraph.
+    Dot,
+    /// Pretty-printed JSON of the [`SupervisionTreeSnapshot`].
+    Json,
+}
+
+/// Lifecycle state of a supervised child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildRunState {
+    /// Registered but not yet running.
+    Starting,
+    /// Running its current incarnation.
+    Running,
+    /// Failed; a restart has been decided but not yet committed.
+    Restarting,
+    /// Not running and not going to be restarted.
+    Stopped,
+}
+
+impl ChildRunState {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Starting => "starting",
+            Self::Running => "running",
+            Self::Restarting => "restarting",
+            Self::Stopped => "stopped",
+        }
+    }
+}
+
+/// What kind of work a child runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildSpecKind {
+    /// A task started from a [`ChildSpec`](super::ChildSpec).
+    Task,
+    /// A supervised actor.
+    Actor,
+}
+
+/// How a supervisor node was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupervisorKind {
+    /// A [`CompiledSupervisor`](super::CompiledSupervisor) tree.
+    Tree,
+    /// The per-actor supervisor of a supervised actor.
+    Actor,
+}
+
+/// Restart-intensity limit: at most `max_restarts` within `window_ns`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartIntensitySnapshot {
+    /// Maximum restarts allowed within the window.
+    pub max_restarts: u32,
+    /// Sliding window length in nanoseconds.
+    pub window_ns: u64,
+}
+
+impl From<&RestartConfig> for RestartIntensitySnapshot {
+    fn from(config: &RestartConfig) -> Self {
+        Self {
+            max_restarts: config.max_restarts,
+            window_ns: u64::try_from(config.window.as_nanos()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// One supervised child in a [`SupervisionTreeSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChildSnapshot {
+    /// Child name.
+    pub name: String,
+    /// Kind of work the child runs.
+    pub spec: ChildSpecKind,
+    /// Restart strategy: `"stop"`, `"restart"` or `"escalate"`.
+    pub restart: String,
+    /// Restart limit when `restart` is `"restart"`.
+    pub intensity: Option<RestartIntensitySnapshot>,
+    /// Current lifecycle state.
+    pub state: ChildRunState,
+    /// Task running the child, if it has one.
+    pub task: Option<IdSnapshot>,
+    /// Number of restarts since the child was first started.
+    pub incarnation: u32,
+    /// Summary of the most recent failure or start problem.
+    pub last_outcome: Option<String>,
+    /// Most recent restart times in nanoseconds, oldest first.
+    pub recent_restarts: Vec<u64>,
+}
+
+/// One supervisor in a [`SupervisionTreeSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupervisorSnapshot {
+    /// Registry id, unique for the runtime's lifetime.
+    pub id: u64,
+    /// Supervisor name.
+    pub name: String,
+    /// How the supervisor was created.
+    pub kind: SupervisorKind,
+    /// Id of the enclosing supervisor, if any.
+    pub parent: Option<u64>,
+    /// Region owning the supervisor's children.
+    pub region: IdSnapshot,
+    /// Restart policy: `"one_for_one"`, `"one_for_all"` or `"rest_for_one"`.
+    pub strategy: String,
+    /// Supervisor-wide restart limit, when the supervisor has one.
+    pub intensity: Option<RestartIntensitySnapshot>,
+    /// Children in start order.
+    pub children: Vec<ChildSnapshot>,
+}
+
+/// Consistent point-in-time view of the supervision hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupervisionTreeSnapshot {
+    /// [`SUPERVISION_TREE_SCHEMA_VERSION`] of this layout.
+    pub schema_version: u32,
+    /// Runtime time of the snapshot in nanoseconds.
+    pub timestamp: u64,
+    /// Supervisors, parents before their nested supervisors.
+    pub supervisors: Vec<SupervisorSnapshot>,
+}
+
+impl SupervisionTreeSnapshot {
+    /// Returns the snapshot as a JSON value.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("supervision tree snapshot serializes")
+    }
+
+    /// Renders the snapshot as a Graphviz digraph.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph supervision {\n");
+        out.push_str("  rankdir=TB;\n  node [fontname=\"monospace\"];\n");
+        for supervisor in &self.supervisors {
+            let mut label = format!(
+                "{}\\n{} {}",
+                escape_dot(&supervisor.name),
+                supervisor.strategy,
+                kind_label(supervisor.kind)
+            );
+            if let Some(intensity) = supervisor.intensity {
+                let _ = write!(label, "\\n{}", intensity_label(intensity));
+            }
+            let _ = write!(label, "\\nregion {}", id_label(supervisor.region));
+            let _ = writeln!(out, "  \"s{}\" [shape=box, label=\"{label}\"];", supervisor.id);
+            if let Some(parent) = supervisor.parent {
+                let _ = writeln!(out, "  \"s{parent}\" -> \"s{}\";", supervisor.id);
+            }
+            for (index, child) in supervisor.children.iter().enumerate() {
+                let mut label = format!(
+                    "{}\\n{} {}",
+                    escape_dot(&child.name),
+                    spec_label(child.spec),
+                    child.restart
+                );
+                if let Some(intensity) = child.intensity {
+                    let _ = write!(label, " {}", intensity_label(intensity));
+                }
+                let _ = write!(label, "\\n{} #{}", child.state.as_str(), child.incarnation);
+                if let Some(task) = child.task {
+                    let _ = write!(label, " task {}", id_label(task));
+                }
+                if let Some(outcome) = &child.last_outcome {
+                    let _ = write!(label, "\\n{}", escape_dot(outcome));
+                }
+                let _ = writeln!(
+                    out,
+                    "  \"s{}.c{index}\" [shape=ellipse, style=filled, fillcolor=\"{}\", label=\"{label}\"];",
+                    supervisor.id,
+                    state_color(child.state)
+                );
+                let _ = writeln!(out, "  \"s{0}\" -> \"s{0}.c{index}\";", supervisor.id);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the snapshot in `format`.
+    #[must_use]
+    pub fn render(&self, format: TreeExportFormat) -> String {
+        match format {
+            TreeExportFormat::Dot => self.to_dot(),
+            TreeExportFormat::Json => {
+                serde_json::to_string_pretty(self).expect("supervision tree snapshot serializes")
+            }
+        }
+    }
+}
+
+const fn kind_label(kind: SupervisorKind) -> &'static str {
+    match kind {
+        SupervisorKind::Tree => "tree",
+        SupervisorKind::Actor => "actor",
+    }
+}
+
+const fn spec_label(spec: ChildSpecKind) -> &'static str {
+    match spec {
+        ChildSpecKind::Task => "task",
+        ChildSpecKind::Actor => "actor",
+    }
+}
+
+fn intensity_label(intensity: RestartIntensitySnapshot) -> String {
+    format!("max {}/{}ms", intensity.max_restarts, intensity.window_ns / 1_000_000)
+}
+
+fn id_label(id: IdSnapshot) -> String {
+    format!("{}:{}", id.index, id.generation)
+}
+
+const fn state_color(state: ChildRunState) -> &'static str {
+    match state {
+        ChildRunState::Starting => "lightyellow",
+        ChildRunState::Running => "palegreen",
+        ChildRunState::Restarting => "orange",
+        ChildRunState::Stopped => "lightgray",
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Takes a snapshot of the runtime's supervision tree and renders it.
+#[must_use]
+pub fn export_tree(state: &RuntimeState, format: TreeExportFormat) -> String {
+    state.supervision_tree().snapshot(state).render(format)
+}
+
+/// Registry of live supervisors, shared by the runtime and supervised tasks.
+///
+/// Obtained with [`RuntimeState::supervision_tree`]. Cloning is cheap.
+#[derive(Clone, Default)]
+pub struct SupervisionTree {
+    inner: Arc<Mutex<TreeState>>,
+}
+
+struct TreeState {
+    next_id: u64,
+    history_limit: usize,
+    supervisors: BTreeMap<u64, SupervisorEntry>,
+}
+
+impl Default for TreeState {
+    fn default() -> Self {
+        Self {
+            next_id: 1,
+            history_limit: DEFAULT_RESTART_HISTORY_LIMIT,
+            supervisors: BTreeMap::new(),
+        }
+    }
+}
+
+struct SupervisorEntry {
+    name: ChildName,
+    kind: SupervisorKind,
+    parent: Option<u64>,
+    region: RegionId,
+    restart_policy: RestartPolicy,
+    intensity: Option<RestartIntensitySnapshot>,
+    children: Vec<ChildEntry>,
+}
+
+struct ChildEntry {
+    name: ChildName,
+    spec: ChildSpecKind,
+    restart: SupervisionStrategy,
+    task: Option<TaskId>,
+    state: ChildRunState,
+    incarnation: u32,
+    last_outcome: Option<String>,
+    recent_restarts: VecDeque<Time>,
+}
+
+/// A child to register with [`SupervisionTree`].
+pub(crate) struct ChildRegistration {
+    pub(crate) name: ChildName,
+    pub(crate) spec: ChildSpecKind,
+    pub(crate) restart: SupervisionStrategy,
+    pub(crate) task: Option<TaskId>,
+    pub(crate) state: ChildRunState,
+    pub(crate) last_outcome: Option<String>,
+}
+
+impl SupervisionTree {
+    /// Sets how many restart timestamps are kept per child (at least one).
+    pub fn set_restart_history_limit(&self, limit: usize) {
+        let mut tree = self.inner.lock();
+        tree.history_limit = limit.max(1);
+        let limit = tree.history_limit;
+        for supervisor in tree.supervisors.values_mut() {
+            for child in &mut supervisor.children {
+                while child.recent_restarts.len() > limit {
+                    child.recent_restarts.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Returns the number of registered supervisors, including any whose
+    /// region has closed but which have not been pruned yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.lock().supervisors.len()
+    }
+
+    /// Returns `true` if no supervisor is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().supervisors.is_empty()
+    }
+
+    /// Takes a consistent snapshot of the tree.
+    ///
+    /// Supervisors whose region no longer exists in `state` are omitted. A
+    /// task child reported as running whose task has completed is exported
+    /// as stopped.
+    #[must_use]
+    pub fn snapshot(&self, state: &RuntimeState) -> SupervisionTreeSnapshot {
+        let tree = self.inner.lock();
+        let supervisors = tree
+            .supervisors
+            .iter()
+            .filter(|(_, supervisor)| state.region(supervisor.region).is_some())
+            .map(|(&id, supervisor)| SupervisorSnapshot {
+                id,
+                name: supervisor.name.to_string(),
+                kind: supervisor.kind,
+                parent: supervisor.parent,
+                region: supervisor.region.into(),
+                strategy: policy_label(supervisor.restart_policy).to_string(),
+                intensity: supervisor.intensity,
+                children: supervisor
+                    .children
+                    .iter()
+                    .map(|child| child_snapshot(child, state))
+                    .collect(),
+            })
+            .collect();
+        drop(tree);
+        SupervisionTreeSnapshot {
+            schema_version: SUPERVISION_TREE_SCHEMA_VERSION,
+            timestamp: state.current_runtime_time().as_nanos(),
+            supervisors,
+        }
+    }
+
+    /// Registers a supervisor owning `region` and returns its id.
+    ///
+    /// The parent is the nearest tree supervisor owning `region` (for actor
+    /// supervisors) or one of its ancestors. Supervisors whose region has
+    /// closed are pruned first.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn register_supervisor(
+        &self,
+        state: &RuntimeState,
+        name: ChildName,
+        kind: SupervisorKind,
+        region: RegionId,
+        restart_policy: RestartPolicy,
+        intensity: Option<RestartIntensitySnapshot>,
+        children: Vec<ChildRegistration>,
+    ) -> u64 {
+        let mut tree = self.inner.lock();
+        tree.supervisors
+            .retain(|_, supervisor| state.region(supervisor.region).is_some());
+
+        let mut search = match kind {
+            SupervisorKind::Actor => Some(region),
+            SupervisorKind::Tree => state.region(region).and_then(|record| record.parent),
+        };
+        let mut parent = None;
+        while let Some(candidate) = search {
+            parent = tree
+                .supervisors
+                .iter()
+                .rev()
+                .find(|(_, supervisor)| {
+                    supervisor.kind == SupervisorKind::Tree && supervisor.region == candidate
+                })
+                .map(|(&id, _)| id);
+            if parent.is_some() {
+                break;
+            }
+            search = state.region(candidate).and_then(|record| record.parent);
+        }
+
+        let id = tree.next_id;
+        tree.next_id += 1;
+        let children = children
+            .into_iter()
+            .map(|child| ChildEntry {
+                name: child.name,
+                spec: child.spec,
+                restart: child.restart,
+                task: child.task,
+                state: child.state,
+                incarnation: 0,
+                last_outcome: child.last_outcome,
+                recent_restarts: VecDeque::new(),
+            })
+            .collect();
+        tree.supervisors.insert(
+            id,
+            SupervisorEntry {
+                name,
+                kind,
+                parent,
+                region,
+                restart_policy,
+                intensity,
+                children,
+            },
+        );
+        id
+    }
+
+    /// Removes a supervisor and its children.
+    pub(crate) fn remove_supervisor(&self, supervisor: u64) {
+        self.inner.lock().supervisors.remove(&supervisor);
+    }
+
+    /// Moves a child to `state`.
+    pub(crate) fn set_child_state(&self, supervisor: u64, child: usize, state: ChildRunState) {
+        self.with_child(supervisor, child, |entry, _| entry.state = state);
+    }
+
+    /// Records a child failure and the state it leaves the child in.
+    pub(crate) fn record_failure(
+        &self,
+        supervisor: u64,
+        child: usize,
+        outcome: String,
+        state: ChildRunState,
+    ) {
+        self.with_child(supervisor, child, |entry, _| {
+            entry.last_outcome = Some(outcome);
+            entry.state = state;
+        });
+    }
+
+    /// Records a committed restart at `now`: the child starts its next
+    /// incarnation and is running again.
+    pub(crate) fn record_restart(&self, supervisor: u64, child: usize, now: Time) {
+        self.with_child(supervisor, child, |entry, limit| {
+            entry.incarnation = entry.incarnation.saturating_add(1);
+            entry.state = ChildRunState::Running;
+            if entry.recent_restarts.len() == limit {
+                entry.recent_restarts.pop_front();
+            }
+            entry.recent_restarts.push_back(now);
+        });
+    }
+
+    fn with_child(
+        &self,
+        supervisor: u64,
+        child: usize,
+        apply: impl FnOnce(&mut ChildEntry, usize),
+    ) {
+        let mut tree = self.inner.lock();
+        let limit = tree.history_limit;
+        if let Some(entry) = tree
+            .supervisors
+            .get_mut(&supervisor)
+            .and_then(|supervisor| supervisor.children.get_mut(child))
+        {
+            apply(entry, limit);
+        }
+    }
+}
+
+impl std::fmt::Debug for SupervisionTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SupervisionTree")
+            .field("supervisors", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+fn child_snapshot(child: &ChildEntry, state: &RuntimeState) -> ChildSnapshot {
+    let (restart, intensity) = match &child.restart {
+        SupervisionStrategy::Stop => ("stop", None),
+        SupervisionStrategy::Restart(config) => ("restart", Some(config.into())),
+        SupervisionStrategy::Escalate => ("escalate", None),
+    };
+    let finished = child.spec == ChildSpecKind::Task
+        && child.state == ChildRunState::Running
+        && child.task.is_some_and(|task| state.task(task).is_none());
+    ChildSnapshot {
+        name: child.name.to_string(),
+        spec: child.spec,
+        restart: restart.to_string(),
+        intensity,
+        state: if finished {
+            ChildRunState::Stopped
+        } else {
+            child.state
+        },
+        task: child.task.map(IdSnapshot::from),
+        incarnation: child.incarnation,
+        last_outcome: child.last_outcome.clone(),
+        recent_restarts: child.recent_restarts.iter().map(|time| time.as_nanos()).collect(),
+    }
+}
+
+const fn policy_label(policy: RestartPolicy) -> &'static str {
+    match policy {
+        RestartPolicy::OneForOne => "one_for_one",
+        RestartPolicy::OneForAll => "one_for_all",
+        RestartPolicy::RestForOne => "rest_for_one",
+    }
+}
+
+/// Registration of a supervised actor, removed from the tree when dropped.
+pub(crate) struct SupervisedActorNode {
+    tree: SupervisionTree,
+    supervisor: u64,
+}
+
+impl SupervisedActorNode {
+    /// Registers the per-actor supervisor of the actor running as `task`.
+    pub(crate) fn register(
+        state: &RuntimeState,
+        region: RegionId,
+        task: TaskId,
+        strategy: &SupervisionStrategy,
+    ) -> Self {
+        let tree = state.supervision_tree().clone();
+        let intensity = match strategy {
+            SupervisionStrategy::Restart(config) => Some(config.into()),
+            SupervisionStrategy::Stop | SupervisionStrategy::Escalate => None,
+        };
+        let supervisor = tree.register_supervisor(
+            state,
+            ChildName::new(format!("actor-{task}")),
+            SupervisorKind::Actor,
+            region,
+            RestartPolicy::OneForOne,
+            intensity,
+            vec![ChildRegistration {
+                name: ChildName::new("actor"),
+                spec: ChildSpecKind::Actor,
+                restart: strategy.clone(),
+                task: Some(task),
+                state: ChildRunState::Starting,
+                last_outcome: None,
+            }],
+        );
+        Self { tree, supervisor }
+    }
+
+    pub(crate) fn set_state(&self, state: ChildRunState) {
+        self.tree.set_child_state(self.supervisor, 0, state);
+    }
+
+    pub(crate) fn record_failure(&self, outcome: String, state: ChildRunState) {
+        self.tree.record_failure(self.supervisor, 0, outcome, state);
+    }
+
+    pub(crate) fn record_restart(&self, now: Time) {
+        self.tree.record_restart(self.supervisor, 0, now);
+    }
+}
+
+impl Drop for SupervisedActorNode {
+    fn drop(&mut self) {
+        self.tree.remove_supervisor(self.supervisor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::actor::Actor;
+    use crate::cx::Cx;
+    use crate::supervision::{BackoffStrategy, ChildSpec, SupervisorBuilder};
+    use crate::types::Budget;
+    use crate::types::policy::FailFast;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn restart(max_restarts: u32, window: Duration) -> SupervisionStrategy {
+        SupervisionStrategy::Restart(
+            RestartConfig::new(max_restarts, window).with_backoff(BackoffStrategy::None),
+        )
+    }
+
+    fn spawn_idle_child(
+        scope: &crate::cx::Scope<'static, FailFast>,
+        state: &mut RuntimeState,
+        cx: &Cx,
+    ) -> Result<TaskId, crate::runtime::SpawnError> {
+        let handle = scope.spawn_registered(state, cx, |_cx| async move { 0u8 })?;
+        Ok(handle.task_id())
+    }
+
+    fn fail_start(
+        _scope: &crate::cx::Scope<'static, FailFast>,
+        _state: &mut RuntimeState,
+        _cx: &Cx,
+    ) -> Result<TaskId, crate::runtime::SpawnError> {
+        Err(crate::runtime::SpawnError::RegionClosed(RegionId::new_for_test(9, 0)))
+    }
+
+    /// Spawns `app` (one_for_all) with a running `db`, a failed optional
+    /// `cache` and a deferred `reporter`.
+    fn spawn_app(state: &mut RuntimeState) -> crate::supervision::SupervisorHandle {
+        let root = state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        SupervisorBuilder::new("app")
+            .with_restart_policy(RestartPolicy::OneForAll)
+            .child(
+                ChildSpec::new("db", spawn_idle_child)
+                    .with_restart(restart(3, Duration::from_secs(60))),
+            )
+            .child(ChildSpec::new("cache", fail_start).with_required(false))
+            .child(
+                ChildSpec::new("reporter", spawn_idle_child)
+                    .with_restart(SupervisionStrategy::Escalate)
+                    .with_start_immediately(false),
+            )
+            .compile()
+            .expect("compile app")
+            .spawn(state, &cx, root, Budget::INFINITE)
+            .expect("spawn app")
+    }
+
+    #[test]
+    fn dot_export_of_constructed_tree() {
+        init_test("dot_export_of_constructed_tree");
+        let mut state = RuntimeState::new();
+        let handle = spawn_app(&mut state);
+        let db_task = handle.started[0].task_id;
+        let region = IdSnapshot::from(handle.region);
+        let task = IdSnapshot::from(db_task);
+
+        let dot = export_tree(&state, TreeExportFormat::Dot);
+        let expected = format!(
+            "digraph supervision {{\n\
+             \x20 rankdir=TB;\n\
+             \x20 node [fontname=\"monospace\"];\n\
+             \x20 \"s1\" [shape=box, label=\"app\\none_for_all tree\\nregion {r}\"];\n\
+             \x20 \"s1.c0\" [shape=ellipse, style=filled, fillcolor=\"palegreen\", label=\"db\\ntask restart max 3/60000ms\\nrunning #0 task {t}\"];\n\
+             \x20 \"s1\" -> \"s1.c0\";\n\
+             \x20 \"s1.c1\" [shape=ellipse, style=filled, fillcolor=\"lightgray\", label=\"cache\\ntask stop\\nstopped #0\\nstart failed: {err}\"];\n\
+             \x20 \"s1\" -> \"s1.c1\";\n\
+             \x20 \"s1.c2\" [shape=ellipse, style=filled, fillcolor=\"lightgray\", label=\"reporter\\ntask escalate\\nstopped #0\\ndeferred\"];\n\
+             \x20 \"s1\" -> \"s1.c2\";\n\
+             }}\n",
+            r = id_label(region),
+            t = id_label(task),
+            err = crate::runtime::SpawnError::RegionClosed(RegionId::new_for_test(9, 0)),
+        );
+        assert_eq!(dot, expected);
+        crate::test_complete!("dot_export_of_constructed_tree");
+    }
+
+    #[test]
+    fn json_schema_is_stable() {
+        init_test("json_schema_is_stable");
+        let mut state = RuntimeState::new();
+        spawn_app(&mut state);
+        let json = state.supervision_tree().snapshot(&state).to_json();
+
+        let keys = |value: &serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<String> = value
+                .as_object()
+                .expect("object")
+                .keys()
+                .cloned()
+                .collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(&json), ["schema_version", "supervisors", "timestamp"]);
+        assert_eq!(json["schema_version"], SUPERVISION_TREE_SCHEMA_VERSION);
+        let supervisor = &json["supervisors"][0];
+        assert_eq!(
+            keys(supervisor),
+            ["children", "id", "intensity", "kind", "name", "parent", "region", "strategy"]
+        );
+        assert_eq!(supervisor["kind"], "tree");
+        assert_eq!(supervisor["strategy"], "one_for_all");
+        assert_eq!(keys(&supervisor["region"]), ["generation", "index"]);
+        let child = &supervisor["children"][0];
+        assert_eq!(
+            keys(child),
+            [
+                "incarnation",
+                "intensity",
+                "last_outcome",
+                "name",
+                "recent_restarts",
+                "restart",
+                "spec",
+                "state",
+                "task"
+            ]
+        );
+        assert_eq!(child["spec"], "task");
+        assert_eq!(child["state"], "running");
+        assert_eq!(keys(&child["intensity"]), ["max_restarts", "window_ns"]);
+
+        let rendered = export_tree(&state, TreeExportFormat::Json);
+        let parsed: SupervisionTreeSnapshot =
+            serde_json::from_str(&rendered).expect("export parses back");
+        assert_eq!(parsed.to_json(), json);
+        crate::test_complete!("json_schema_is_stable");
+    }
+
+    #[test]
+    fn restart_history_is_bounded() {
+        init_test("restart_history_is_bounded");
+        let mut state = RuntimeState::new();
+        let region = state.create_root_region(Budget::INFINITE);
+        let tree = state.supervision_tree().clone();
+        tree.set_restart_history_limit(4);
+        let node = SupervisedActorNode::register(
+            &state,
+            region,
+            TaskId::new_for_test(1, 0),
+            &restart(100, Duration::from_secs(1)),
+        );
+        for restart in 1..=10_u64 {
+            node.record_failure(format!("panicked #{restart}"), ChildRunState::Restarting);
+            node.record_restart(Time::from_millis(restart));
+        }
+
+        let snapshot = tree.snapshot(&state);
+        let child = &snapshot.supervisors[0].children[0];
+        assert_eq!(child.incarnation, 10);
+        assert_eq!(child.state, ChildRunState::Running);
+        assert_eq!(child.last_outcome.as_deref(), Some("panicked #10"));
+        assert_eq!(
+            child.recent_restarts,
+            vec![7_000_000, 8_000_000, 9_000_000, 10_000_000]
+        );
+
+        tree.set_restart_history_limit(2);
+        let child = tree.snapshot(&state).supervisors[0].children[0].clone();
+        assert_eq!(child.recent_restarts, vec![9_000_000, 10_000_000]);
+
+        drop(node);
+        assert!(tree.is_empty());
+        crate::test_complete!("restart_history_is_bounded");
+    }
+
+    #[derive(Debug)]
+    struct Flaky {
+        panic_on: u64,
+    }
+
+    impl Actor for Flaky {
+        type Message = u64;
+
+        fn handle(&mut self, _cx: &Cx, msg: u64) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+            assert!(msg != self.panic_on, "flaky actor crashed on {msg}");
+            Box::pin(async {})
+        }
+    }
+
+    #[test]
+    fn export_is_consistent_under_concurrent_restarts() {
+        init_test("export_is_consistent_under_concurrent_restarts");
+        let mut runtime = crate::lab::LabRuntime::with_seed(0x5EED_7EE);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(region, Budget::INFINITE);
+
+        let mut actors = Vec::new();
+        for _ in 0..3 {
+            let starts = Arc::new(AtomicU32::new(0));
+            let counter = Arc::clone(&starts);
+            let (handle, stored) = scope
+                .spawn_supervised_actor(
+                    &mut runtime.state,
+                    &cx,
+                    move || {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Flaky { panic_on: 0 }
+                    },
+                    restart(10, Duration::from_secs(60)),
+                    16,
+                )
+                .expect("spawn supervised actor");
+            let task_id = handle.task_id();
+            runtime.state.store_spawned_task(task_id, stored);
+            runtime.scheduler.lock().schedule(task_id, 0);
+            actors.push((handle, starts, IdSnapshot::from(task_id)));
+        }
+        for (index, (handle, _, _)) in actors.iter().enumerate() {
+            for _ in 0..=index {
+                handle.try_send(1).expect("send");
+                handle.try_send(0).expect("send crash");
+            }
+        }
+
+        let mut restarts_seen = 0;
+        for _ in 0..500 {
+            runtime.step_for_test();
+            let snapshot = runtime.state.supervision_tree().snapshot(&runtime.state);
+            assert_eq!(snapshot.supervisors.len(), actors.len());
+            for (handle, starts, task) in &actors {
+                let supervisor = snapshot
+                    .supervisors
+                    .iter()
+                    .find(|supervisor| supervisor.children[0].task == Some(*task))
+                    .expect("actor exported");
+                let child = &supervisor.children[0];
+                let starts = starts.load(Ordering::SeqCst);
+                assert_eq!(
+                    child.incarnation,
+                    starts.saturating_sub(1),
+                    "actor {:?}: export must match the factory count at the same instant",
+                    handle.task_id()
+                );
+                assert_eq!(child.recent_restarts.len() as u32, child.incarnation);
+                if child.incarnation > 0 {
+                    assert!(child.last_outcome.is_some());
+                }
+                restarts_seen = restarts_seen.max(child.incarnation);
+            }
+        }
+        assert_eq!(restarts_seen, 3, "every scripted crash restarted its actor");
+
+        for (handle, _, _) in &actors {
+            handle.abort();
+        }
+        runtime.run_until_quiescent();
+        assert!(runtime.state.supervision_tree().snapshot(&runtime.state).supervisors.is_empty());
+        crate::test_complete!("export_is_consistent_under_concurrent_restarts");
+    }
+
+    #[test]
+    fn region_ids_correlate_with_runtime_snapshot() {
+        init_test("region_ids_correlate_with_runtime_snapshot");
+        let mut state = RuntimeState::new();
+        let handle = spawn_app(&mut state);
+        let cx = Cx::for_testing();
+        let scope = crate::cx::Scope::<FailFast>::new(handle.region, Budget::INFINITE);
+        let (actor_handle, stored) = scope
+            .spawn_supervised_actor(
+                &mut state,
+                &cx,
+                || Flaky { panic_on: 0 },
+                SupervisionStrategy::Stop,
+                4,
+            )
+            .expect("spawn actor in app region");
+        let actor_task = actor_handle.task_id();
+        state.store_spawned_task(actor_task, stored);
+
+        let tree = state.supervision_tree().snapshot(&state);
+        let runtime = state.snapshot();
+        assert_eq!(tree.supervisors.len(), 2);
+        for supervisor in &tree.supervisors {
+            assert!(
+                runtime.regions.iter().any(|region| region.id == supervisor.region),
+                "supervisor {} region missing from region snapshot",
+                supervisor.name
+            );
+        }
+        let app = &tree.supervisors[0];
+        let actor = &tree.supervisors[1];
+        assert_eq!(app.region, IdSnapshot::from(handle.region));
+        assert_eq!(actor.kind, SupervisorKind::Actor);
+        assert_eq!(actor.parent, Some(app.id));
+        assert_eq!(actor.region, app.region);
+        assert_eq!(actor.children[0].task, Some(IdSnapshot::from(actor_task)));
+        assert!(runtime.tasks.iter().any(|task| Some(task.id) == actor.children[0].task));
+        crate::test_complete!("region_ids_correlate_with_runtime_snapshot");
+    }
+}
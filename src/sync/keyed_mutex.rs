@@ -0,0 +1,709 @@
+//! Per-key mutual exclusion over an unbounded key space.
+//!
+//! [`KeyedMutex`] serializes critical sections per entity (a user id, an
+//! order id) while sections for different keys run in parallel. It is the
+//! middle ground between one global [`Mutex`](super::Mutex), which serializes
+//! everything, and a map of mutexes, which grows with every key ever seen.
+//!
+//! # Memory
+//!
+//! Keys are spread over a fixed number of striped maps. A key has an entry
+//! only while it is held: the entry records the holder and the key's FIFO
+//! waiter queue, and it is removed when the last holder releases with no one
+//! waiting. An idle keyed mutex therefore tracks zero keys no matter how many
+//! it has seen.
+//!
+//! # Fairness
+//!
+//! Waiters for one key are served strictly FIFO. Release hands the key
+//! directly to the next waiter, so [`KeyedMutex::try_lock`] never barges
+//! past a queue.
+//!
+//! # Cancel Safety
+//!
+//! A waiter that is cancelled or dropped leaves its key's queue. If the key
+//! was handed to it in the meantime, it passes the key on to the next waiter
+//! (or frees it), so the key's state is never orphaned.
+//!
+//! # Multiple keys
+//!
+//! [`KeyedMutex::lock_many`] sorts and deduplicates the keys, then locks them
+//! in ascending order. Every multi-key acquisition uses the same canonical
+//! order, so two of them can never wait on each other in a cycle. Keys
+//! already taken are released if the acquisition is cancelled part way.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::sync::KeyedMutex;
+//!
+//! let orders = KeyedMutex::new();
+//!
+//! let _order = orders.lock(&cx, order_id).await?;
+//! // Exclusive for `order_id`; other orders proceed in parallel.
+//!
+//! let _both = orders.lock_many(&cx, [from_account, to_account]).await?;
+//! ```
+
+use parking_lot::Mutex;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::hash::{BuildHasher, Hash};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll, Waker};
+
+use super::LockError;
+use crate::cx::Cx;
+
+const DEFAULT_STRIPES: usize = 64;
+
+/// Number of buckets in [`KeyedMutexMetrics::contention`].
+pub const KEYED_CONTENTION_BUCKETS: usize = 6;
+
+/// Maps the number of holders and waiters ahead of an acquisition to its
+/// contention bucket: 0, 1, 2-3, 4-7, 8-15, 16 or more.
+fn contention_bucket(ahead: usize) -> usize {
+    if ahead == 0 {
+        0
+    } else {
+        (ahead.ilog2() as usize + 1).min(KEYED_CONTENTION_BUCKETS - 1)
+    }
+}
+
+struct Waiter {
+    id: u64,
+    waker: Waker,
+}
+
+/// A held key: its current holder and the waiters queued behind it.
+struct KeyState {
+    holder: u64,
+    waiters: VecDeque<Waiter>,
+}
+
+/// Hands `key` from its holder to the next waiter, or frees it. Returns the
+/// new holder's waker.
+fn hand_off<K: Hash + Eq>(keys: &mut HashMap<K, KeyState>, key: &K) -> Option<Waker> {
+    let state = keys.get_mut(key)?;
+    if let Some(next) = state.waiters.pop_front() {
+        state.holder = next.id;
+        Some(next.waker)
+    } else {
+        keys.remove(key);
+        None
+    }
+}
+
+/// Snapshot of a [`KeyedMutex`]'s load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyedMutexMetrics {
+    /// Keys currently held. Only held keys are tracked, so this is also the
+    /// number of keys with allocated state.
+    pub keys_held: usize,
+    /// Acquisitions waiting for a held key.
+    pub waiters: usize,
+    /// Total successful acquisitions.
+    pub acquisitions: u64,
+    /// Waits abandoned by cancellation or drop.
+    pub cancellations: u64,
+    /// Acquisitions by the number of holders and waiters ahead of them when
+    /// they arrived: 0, 1, 2-3, 4-7, 8-15, 16 or more.
+    pub contention: [u64; KEYED_CONTENTION_BUCKETS],
+}
+
+/// A mutex per key, with state only for keys that are currently held.
+///
+/// See the [module documentation](self) for fairness, memory and
+/// cancellation guarantees. The mutex guards no data, so a panic while
+/// holding a key simply releases it; there is no poisoning.
+pub struct KeyedMutex<K> {
+    stripes: Box<[Mutex<HashMap<K, KeyState>>]>,
+    hasher: RandomState,
+    next_id: AtomicU64,
+    acquisitions: AtomicU64,
+    cancellations: AtomicU64,
+    contention: [AtomicU64; KEYED_CONTENTION_BUCKETS],
+}
+
+impl<K> fmt::Debug for KeyedMutex<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedMutex")
+            .field("stripes", &self.stripes.len())
+            .field("acquisitions", &self.acquisitions.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedMutex<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedMutex<K> {
+    /// Creates a keyed mutex with the default number of stripes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_stripes(DEFAULT_STRIPES)
+    }
+
+    /// Creates a keyed mutex whose keys are spread over `stripes` maps (at
+    /// least one). More stripes lower contention on the internal maps
+    /// between unrelated keys.
+    #[must_use]
+    pub fn with_stripes(stripes: usize) -> Self {
+        Self {
+            stripes: (0..stripes.max(1))
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+            next_id: AtomicU64::new(0),
+            acquisitions: AtomicU64::new(0),
+            cancellations: AtomicU64::new(0),
+            contention: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    fn stripe(&self, key: &K) -> &Mutex<HashMap<K, KeyState>> {
+        let index = self.hasher.hash_one(key) as usize % self.stripes.len();
+        &self.stripes[index]
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn record_acquire(&self, ahead: usize) {
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.contention[contention_bucket(ahead)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Waits until `key` is free, then holds it until the guard is dropped.
+    ///
+    /// Waiters for the same key are served FIFO. Fails with
+    /// [`LockError::Cancelled`] if `cx` is cancelled while waiting; never
+    /// with [`LockError::Poisoned`].
+    pub fn lock<'a, 'b, Caps>(
+        &'a self,
+        cx: &'b Cx<Caps>,
+        key: K,
+    ) -> KeyedLockFuture<'a, 'b, K, Caps> {
+        KeyedLockFuture {
+            mutex: self,
+            cx,
+            key,
+            id: self.next_id(),
+            ahead: 0,
+            queued: false,
+            completed: false,
+        }
+    }
+
+    /// Takes `key` if it is neither held nor awaited.
+    pub fn try_lock(&self, key: K) -> Option<KeyedMutexGuard<'_, K>> {
+        let id = self.next_id();
+        let mut keys = self.stripe(&key).lock();
+        if keys.contains_key(&key) {
+            return None;
+        }
+        keys.insert(
+            key.clone(),
+            KeyState {
+                holder: id,
+                waiters: VecDeque::new(),
+            },
+        );
+        drop(keys);
+        self.record_acquire(0);
+        Some(KeyedMutexGuard { mutex: self, key })
+    }
+
+    /// Locks every key in `keys`, in ascending order.
+    ///
+    /// Duplicates are locked once. Because every caller locks in the same
+    /// canonical order, concurrent `lock_many` calls over overlapping key
+    /// sets cannot deadlock. On cancellation the keys taken so far are
+    /// released and [`LockError::Cancelled`] is returned.
+    pub async fn lock_many<Caps>(
+        &self,
+        cx: &Cx<Caps>,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<KeyedMutexManyGuard<'_, K>, LockError>
+    where
+        K: Ord,
+    {
+        let mut keys: Vec<K> = keys.into_iter().collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let mut guards = Vec::with_capacity(keys.len());
+        for key in keys {
+            // On error the guards taken so far drop and release their keys.
+            guards.push(self.lock(cx, key).await?);
+        }
+        Ok(KeyedMutexManyGuard { guards })
+    }
+
+    /// Returns `true` if `key` is currently held.
+    #[must_use]
+    pub fn is_locked(&self, key: &K) -> bool {
+        self.stripe(key).lock().contains_key(key)
+    }
+
+    /// Number of keys with allocated state, which is the number held.
+    #[must_use]
+    pub fn tracked_keys(&self) -> usize {
+        self.stripes.iter().map(|stripe| stripe.lock().len()).sum()
+    }
+
+    /// Returns a snapshot of held keys, waiters and contention counters.
+    #[must_use]
+    pub fn metrics(&self) -> KeyedMutexMetrics {
+        let mut metrics = KeyedMutexMetrics {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            cancellations: self.cancellations.load(Ordering::Relaxed),
+            contention: std::array::from_fn(|bucket| {
+                self.contention[bucket].load(Ordering::Relaxed)
+            }),
+            ..KeyedMutexMetrics::default()
+        };
+        for stripe in &*self.stripes {
+            let keys = stripe.lock();
+            metrics.keys_held += keys.len();
+            metrics.waiters += keys.values().map(|state| state.waiters.len()).sum::<usize>();
+        }
+        metrics
+    }
+
+    fn release(&self, key: &K) {
+        let next = hand_off(&mut self.stripe(key).lock(), key);
+        if let Some(next) = next {
+            next.wake();
+        }
+    }
+}
+
+/// Future returned by [`KeyedMutex::lock`].
+pub struct KeyedLockFuture<'a, 'b, K: Hash + Eq + Clone, Caps = crate::cx::cap::All> {
+    mutex: &'a KeyedMutex<K>,
+    cx: &'b Cx<Caps>,
+    key: K,
+    id: u64,
+    ahead: usize,
+    queued: bool,
+    completed: bool,
+}
+
+impl<K: Hash + Eq + Clone, Caps> KeyedLockFuture<'_, '_, K, Caps> {
+    /// Leaves the key's queue. If the key was already handed to this waiter,
+    /// passes it on instead.
+    fn leave(&mut self) {
+        if !std::mem::take(&mut self.queued) {
+            return;
+        }
+        let mut keys = self.mutex.stripe(&self.key).lock();
+        let granted = keys
+            .get(&self.key)
+            .is_some_and(|state| state.holder == self.id);
+        let next = if granted {
+            hand_off(&mut keys, &self.key)
+        } else {
+            if let Some(state) = keys.get_mut(&self.key) {
+                state.waiters.retain(|waiter| waiter.id != self.id);
+            }
+            None
+        };
+        drop(keys);
+        self.mutex.cancellations.fetch_add(1, Ordering::Relaxed);
+        if let Some(next) = next {
+            next.wake();
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, Caps> Drop for KeyedLockFuture<'_, '_, K, Caps> {
+    fn drop(&mut self) {
+        self.leave();
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, Caps> Future for KeyedLockFuture<'a, '_, K, Caps> {
+    type Output = Result<KeyedMutexGuard<'a, K>, LockError>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if this.completed {
+            return Poll::Ready(Err(LockError::PolledAfterCompletion));
+        }
+        if this.cx.checkpoint().is_err() {
+            this.completed = true;
+            this.leave();
+            return Poll::Ready(Err(LockError::Cancelled));
+        }
+
+        let mut keys = this.mutex.stripe(&this.key).lock();
+        let acquired = match keys.get_mut(&this.key) {
+            None => {
+                debug_assert!(!this.queued, "a queued key stays tracked until handed over");
+                keys.insert(
+                    this.key.clone(),
+                    KeyState {
+                        holder: this.id,
+                        waiters: VecDeque::new(),
+                    },
+                );
+                true
+            }
+            Some(state) if state.holder == this.id => true,
+            Some(state) if this.queued => {
+                if let Some(waiter) = state.waiters.iter_mut().find(|w| w.id == this.id)
+                    && !waiter.waker.will_wake(context.waker())
+                {
+                    waiter.waker = context.waker().clone();
+                }
+                false
+            }
+            Some(state) => {
+                this.ahead = 1 + state.waiters.len();
+                state.waiters.push_back(Waiter {
+                    id: this.id,
+                    waker: context.waker().clone(),
+                });
+                this.queued = true;
+                false
+            }
+        };
+        drop(keys);
+        if !acquired {
+            return Poll::Pending;
+        }
+        this.queued = false;
+        this.completed = true;
+        this.mutex.record_acquire(this.ahead);
+        Poll::Ready(Ok(KeyedMutexGuard {
+            mutex: this.mutex,
+            key: this.key.clone(),
+        }))
+    }
+}
+
+/// Holds one key of a [`KeyedMutex`]; dropping it releases the key.
+#[must_use = "the key is released as soon as the guard is dropped"]
+pub struct KeyedMutexGuard<'a, K: Hash + Eq + Clone> {
+    mutex: &'a KeyedMutex<K>,
+    key: K,
+}
+
+impl<K: Hash + Eq + Clone> KeyedMutexGuard<'_, K> {
+    /// The held key.
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: Hash + Eq + Clone + fmt::Debug> fmt::Debug for KeyedMutexGuard<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedMutexGuard")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Drop for KeyedMutexGuard<'_, K> {
+    fn drop(&mut self) {
+        self.mutex.release(&self.key);
+    }
+}
+
+/// Holds every key locked by [`KeyedMutex::lock_many`]; dropping it
+/// releases them all.
+#[must_use = "the keys are released as soon as the guard is dropped"]
+pub struct KeyedMutexManyGuard<'a, K: Hash + Eq + Clone> {
+    guards: Vec<KeyedMutexGuard<'a, K>>,
+}
+
+impl<K: Hash + Eq + Clone> KeyedMutexManyGuard<'_, K> {
+    /// The held keys, in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.guards.iter().map(KeyedMutexGuard::key)
+    }
+}
+
+impl<K: Hash + Eq + Clone + fmt::Debug> fmt::Debug for KeyedMutexManyGuard<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lab::{AutoAdvanceTermination, LabRuntime};
+    use crate::runtime::yield_now;
+    use crate::test_utils::spawn_at;
+    use crate::time::{sleep_until, timeout};
+    use crate::types::{Budget, Time};
+    use futures_lite::future::block_on;
+    use proptest::prelude::*;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Option<F::Output> {
+        let mut context = Context::from_waker(Waker::noop());
+        match future.poll(&mut context) {
+            Poll::Ready(output) => Some(output),
+            Poll::Pending => None,
+        }
+    }
+
+    /// Critical sections in progress, checked for per-key exclusion.
+    #[derive(Debug, Default)]
+    struct Sections {
+        inside: Mutex<Vec<u32>>,
+        peak_keys: Mutex<usize>,
+        completed: Mutex<usize>,
+    }
+
+    impl Sections {
+        fn enter(&self, keys: &[u32]) {
+            let mut inside = self.inside.lock();
+            for key in keys {
+                assert!(!inside.contains(key), "two holders inside key {key}");
+            }
+            inside.extend_from_slice(keys);
+            let mut peak = self.peak_keys.lock();
+            *peak = (*peak).max(inside.len());
+        }
+
+        fn exit(&self, keys: &[u32]) {
+            self.inside.lock().retain(|key| !keys.contains(key));
+            *self.completed.lock() += 1;
+        }
+    }
+
+    /// Runs one task per key set; each holds its keys across a few yields.
+    fn run_sections(seed: u64, key_sets: Vec<Vec<u32>>) -> (Arc<KeyedMutex<u32>>, Arc<Sections>) {
+        let mutex = Arc::new(KeyedMutex::new());
+        let sections = Arc::new(Sections::default());
+        let mut runtime = LabRuntime::with_seed(seed);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        for keys in key_sets {
+            let mutex = Arc::clone(&mutex);
+            let sections = Arc::clone(&sections);
+            spawn_at(&mut runtime, root, Budget::INFINITE, 0, move |cx| async move {
+                let guard = mutex.lock_many(&cx, keys).await.expect("lock_many");
+                let held: Vec<u32> = guard.keys().copied().collect();
+                sections.enter(&held);
+                for _ in 0..3 {
+                    yield_now().await;
+                }
+                sections.exit(&held);
+            });
+        }
+        runtime.run_until_quiescent();
+        (mutex, sections)
+    }
+
+    #[test]
+    fn same_key_excludes_while_other_keys_run_in_parallel() {
+        init_test("same_key_excludes_while_other_keys_run_in_parallel");
+        for seed in 0..16 {
+            let key_sets = (0..12).map(|task| vec![task % 4]).collect();
+            let (mutex, sections) = run_sections(seed, key_sets);
+
+            assert_eq!(*sections.completed.lock(), 12);
+            let peak = *sections.peak_keys.lock();
+            crate::assert_with_log!(peak > 1, "distinct keys held at once", "> 1", peak);
+            let metrics = mutex.metrics();
+            assert_eq!(metrics.acquisitions, 12);
+            assert!(metrics.contention[1..].iter().sum::<u64>() > 0, "same-key tasks queued");
+            assert_eq!((metrics.keys_held, metrics.waiters), (0, 0));
+            assert_eq!(mutex.tracked_keys(), 0, "seed {seed}: key state reclaimed");
+        }
+        crate::test_complete!("same_key_excludes_while_other_keys_run_in_parallel");
+    }
+
+    #[test]
+    fn waiters_for_a_key_are_served_fifo() {
+        init_test("waiters_for_a_key_are_served_fifo");
+        let mutex = Arc::new(KeyedMutex::new());
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let probe = Arc::new(Mutex::new(None));
+        let mut runtime = LabRuntime::with_seed(7);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+
+        let holder = Arc::clone(&mutex);
+        spawn_at(&mut runtime, root, Budget::INFINITE, 0, move |cx| async move {
+            let _guard = holder.lock(&cx, 1).await.expect("holder");
+            sleep_until(Time::from_millis(10)).await;
+        });
+        for label in 1..=4 {
+            let mutex = Arc::clone(&mutex);
+            let order = Arc::clone(&order);
+            spawn_at(&mut runtime, root, Budget::INFINITE, label, move |cx| async move {
+                let _guard = mutex.lock(&cx, 1).await.expect("waiter");
+                order.lock().push((label, cx.now().as_millis()));
+                sleep_until(cx.now() + Duration::from_millis(1)).await;
+            });
+        }
+        let prober = Arc::clone(&mutex);
+        let probe_slot = Arc::clone(&probe);
+        spawn_at(&mut runtime, root, Budget::INFINITE, 5, move |_cx| async move {
+            let barged = prober.try_lock(1).is_some();
+            let other_key = prober.try_lock(2).is_some();
+            *probe_slot.lock() = Some((barged, other_key, prober.metrics().waiters));
+        });
+        let report = runtime.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::Quiescent);
+
+        assert_eq!(*probe.lock(), Some((false, true, 4)));
+        let order = order.lock().clone();
+        let expected = vec![(1, 10), (2, 11), (3, 12), (4, 13)];
+        crate::assert_with_log!(order == expected, "grant order", expected, order);
+        let metrics = mutex.metrics();
+        assert_eq!(metrics.contention, [2, 1, 2, 1, 0, 0]);
+        assert_eq!(mutex.tracked_keys(), 0);
+        crate::test_complete!("waiters_for_a_key_are_served_fifo");
+    }
+
+    #[test]
+    fn timed_out_waiter_leaves_the_queue() {
+        init_test("timed_out_waiter_leaves_the_queue");
+        let mutex = Arc::new(KeyedMutex::new());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut runtime = LabRuntime::with_seed(9);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+
+        let holder = Arc::clone(&mutex);
+        spawn_at(&mut runtime, root, Budget::INFINITE, 0, move |cx| async move {
+            let _guard = holder.lock(&cx, 3).await.expect("holder");
+            sleep_until(Time::from_millis(50)).await;
+        });
+        for (label, wait_ms) in [(1, 10), (2, 100)] {
+            let mutex = Arc::clone(&mutex);
+            let log = Arc::clone(&log);
+            spawn_at(&mut runtime, root, Budget::INFINITE, label, move |cx| async move {
+                let wait = Duration::from_millis(wait_ms);
+                let outcome = timeout(cx.now(), wait, mutex.lock(&cx, 3)).await;
+                log.lock().push((label, outcome.is_ok(), cx.now().as_millis()));
+            });
+        }
+        let report = runtime.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::Quiescent);
+
+        let log = log.lock().clone();
+        let expected = vec![(1, false, 11), (2, true, 50)];
+        crate::assert_with_log!(log == expected, "waiter outcomes", expected, log);
+        let metrics = mutex.metrics();
+        assert_eq!(metrics.cancellations, 1);
+        assert_eq!((metrics.keys_held, metrics.waiters), (0, 0));
+        crate::test_complete!("timed_out_waiter_leaves_the_queue");
+    }
+
+    #[test]
+    fn abandoned_waiters_never_orphan_a_key() {
+        init_test("abandoned_waiters_never_orphan_a_key");
+        let mutex = KeyedMutex::new();
+        let cx = Cx::for_testing();
+        let cancelled_cx = Cx::for_testing();
+        let holder = mutex.try_lock(7).expect("free key");
+
+        let mut cancelled = Box::pin(mutex.lock(&cancelled_cx, 7));
+        let mut handed = Box::pin(mutex.lock(&cx, 7));
+        let mut last = Box::pin(mutex.lock(&cx, 7));
+        assert!(poll_once(cancelled.as_mut()).is_none());
+        assert!(poll_once(handed.as_mut()).is_none());
+        assert!(poll_once(last.as_mut()).is_none());
+        assert_eq!(mutex.metrics().waiters, 3);
+
+        cancelled_cx.set_cancel_requested(true);
+        let outcome = poll_once(cancelled.as_mut());
+        assert!(matches!(outcome, Some(Err(LockError::Cancelled))));
+        assert_eq!(mutex.metrics().waiters, 2);
+
+        // The key is handed to a waiter that is dropped before it observes
+        // the grant; the key must move on rather than stay held.
+        drop(holder);
+        drop(handed);
+        let guard = poll_once(last.as_mut()).expect("key passed on").expect("lock");
+        assert_eq!(*guard.key(), 7);
+        drop(guard);
+
+        assert_eq!(mutex.tracked_keys(), 0);
+        assert_eq!(mutex.metrics().cancellations, 2);
+        crate::test_complete!("abandoned_waiters_never_orphan_a_key");
+    }
+
+    #[test]
+    fn key_state_is_reclaimed_after_release() {
+        init_test("key_state_is_reclaimed_after_release");
+        let mutex = KeyedMutex::with_stripes(8);
+        let cx = Cx::for_testing();
+        let guards: Vec<_> = (0..1_000_u64)
+            .map(|key| block_on(mutex.lock(&cx, key)).expect("free key"))
+            .collect();
+        assert_eq!(mutex.tracked_keys(), 1_000);
+        assert!(mutex.is_locked(&999));
+        drop(guards);
+        assert_eq!(mutex.tracked_keys(), 0);
+
+        for key in 0..10 {
+            drop(mutex.try_lock(key * 7_919).expect("free key"));
+        }
+        let expected = KeyedMutexMetrics {
+            acquisitions: 1_010,
+            contention: [1_010, 0, 0, 0, 0, 0],
+            ..KeyedMutexMetrics::default()
+        };
+        assert_eq!(mutex.metrics(), expected);
+        crate::test_complete!("key_state_is_reclaimed_after_release");
+    }
+
+    #[test]
+    fn panicking_holder_releases_its_key() {
+        init_test("panicking_holder_releases_its_key");
+        let mutex = KeyedMutex::new();
+        let cx = Cx::for_testing();
+        let mut waiter = Box::pin(mutex.lock(&cx, "order-1"));
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = mutex.try_lock("order-1").expect("free key");
+            assert!(poll_once(waiter.as_mut()).is_none());
+            panic!("handler failed inside the critical section");
+        }));
+        assert!(result.is_err());
+
+        let guard = poll_once(waiter.as_mut()).expect("handed over").expect("lock");
+        drop(guard);
+        assert!(!mutex.is_locked(&"order-1"));
+        assert!(mutex.try_lock("order-1").is_some(), "no poisoning");
+        assert_eq!(mutex.tracked_keys(), 0);
+        crate::test_complete!("panicking_holder_releases_its_key");
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        /// Tasks lock overlapping key sets listed in arbitrary orders; the
+        /// canonical lock order means every task finishes.
+        #[test]
+        fn lock_many_never_deadlocks_under_adversarial_orders(
+            seed in any::<u64>(),
+            key_sets in prop::collection::vec(prop::collection::vec(0_u32..6, 1..5), 2..8),
+        ) {
+            let tasks = key_sets.len();
+            let (mutex, sections) = run_sections(seed, key_sets);
+            prop_assert_eq!(*sections.completed.lock(), tasks);
+            prop_assert_eq!(mutex.tracked_keys(), 0);
+        }
+    }
+}
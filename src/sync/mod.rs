@@ -6,13 +6,21 @@
 //! # Primitives
 //!
 //! - [`Mutex`]: Mutual exclusion with guard obligations
+//! - [`KeyedMutex`]: Per-key mutual exclusion with state only for held keys
 //! - [`RwLock`]: Read-write lock with cancel-aware acquisition
 //! - [`Semaphore`]: Counting semaphore with permit obligations
 //! - [`Pool`]: Resource pooling with obligation-based return semantics
+//! - [`RateLimiter`]: Shared token-bucket / GCRA rate limiter with FIFO async acquire
 //! - [`Barrier`]: N-way rendezvous with leader election
 //! - [`Notify`]: Event signaling (one-shot or broadcast)
 //! - [`OnceCell`]: Lazy initialization cell
 //!
+//! [`Mutex`] and the write side of [`RwLock`] optionally apply priority
+//! inheritance so a low-priority holder cannot starve a high-priority waiter.
+//!
+//! A panic while holding a [`Mutex`] or a [`RwLock`] write guard poisons the
+//! lock; [`PoisonError`] carries the guard for callers that repair the value.
+//!
 //! # Two-Phase Pattern
 //!
 //! All primitives in this module follow a two-phase pattern:
@@ -60,6 +68,7 @@ pub struct SyncTelemetrySnapshot {
 
 mod barrier;
 mod contended_mutex;
+mod keyed_mutex;
 #[cfg(test)]
 mod cross_module_lock_ordering_test;
 pub mod lock_ordering;
@@ -74,9 +83,12 @@ mod notify_metamorphic;
 mod once_cell;
 #[cfg(test)]
 mod once_cell_metamorphic;
+mod poison;
 mod pool;
 #[cfg(test)]
 mod pool_metamorphic_tests;
+mod priority_inheritance;
+mod rate_limiter;
 mod rwlock;
 #[cfg(test)]
 mod rwlock_lost_wakeup_test;
@@ -89,15 +101,25 @@ mod waiter;
 
 pub use barrier::{Barrier, BarrierWaitError, BarrierWaitResult};
 pub use contended_mutex::{ContendedMutex, ContendedMutexGuard, LockMetricsSnapshot};
+pub use keyed_mutex::{
+    KEYED_CONTENTION_BUCKETS, KeyedLockFuture, KeyedMutex, KeyedMutexGuard, KeyedMutexManyGuard,
+    KeyedMutexMetrics,
+};
 pub use mutex::{LockError, Mutex, MutexGuard, OwnedMutexGuard, TryLockError};
 pub use notify::{Notified, Notify};
 pub use once_cell::{OnceCell, OnceCellError};
+pub use poison::{LockPoisonEvent, LockResult, PoisonError};
 pub use pool::{
     AsyncResourceFactory, DestroyReason, GenericPool, Pool, PoolConfig, PoolError, PoolFuture,
     PoolReturn, PoolReturnReceiver, PoolReturnSender, PoolStats, PooledResource, WarmupStrategy,
 };
 #[cfg(feature = "metrics")]
 pub use pool::{PoolMetrics, PoolMetricsHandle, PoolMetricsState};
+pub(crate) use priority_inheritance::TaskPriorityNode;
+pub use priority_inheritance::{
+    DEFAULT_MAX_INHERITANCE_DEPTH, PriorityInheritanceEvent, PriorityInheritanceEventKind,
+};
+pub use rate_limiter::{RateAcquireError, RateAcquireFuture, RateAlgorithm, RateLimit, RateLimiter};
 pub use rwlock::{
     OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock, RwLockError, RwLockReadGuard,
     RwLockWriteGuard, TryReadError, TryWriteError,
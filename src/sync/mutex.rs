@@ -10,6 +10,20 @@
 //! - **Phase 1**: Wait for lock availability (cancel-safe)
 //! - **Phase 2**: Acquire lock and return a guard (cannot fail)
 //!
+//! # Priority Inheritance
+//!
+//! A mutex built with [`Mutex::with_priority_inheritance`] lends the priority
+//! of its highest-priority waiter to the current holder until the guard is
+//! dropped, forwarding the boost through nested inheriting locks.
+//!
+//! # Poisoning
+//!
+//! A guard dropped during a panic poisons the mutex. Ordinary acquisition
+//! then fails with [`LockError::Poisoned`]; [`Mutex::lock_recoverable`] hands
+//! the guard back in a [`PoisonError`] for repair, after which
+//! [`Mutex::clear_poison`] re-admits other acquirers. [`Mutex::new_unpoisoned`]
+//! opts out.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -30,14 +44,17 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
 use std::ops::{Deref, DerefMut};
+use std::panic::Location;
 use std::pin::Pin;
 use std::ptr::NonNull;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::{Context, Poll, Waker};
 
+use super::poison::{LockPoisonEvent, LockResult, PoisonError};
 use crate::cx::Cx;
 use crate::sync::lock_ordering::{self, LockRank};
+use crate::sync::priority_inheritance::{InheritanceWait, PriorityInheritance};
 use crate::time::Sleep;
 use crate::types::Time;
 
@@ -96,12 +113,18 @@ pub struct Mutex<T> {
     data: UnsafeCell<T>,
     /// Whether the mutex is poisoned.
     poisoned: AtomicBool,
+    /// Whether a panicking holder poisons the mutex.
+    poisoning: bool,
+    /// Source location that constructed the mutex.
+    site: &'static Location<'static>,
     /// Internal state for fairness and locking.
     state: ParkingMutex<MutexState>,
     /// Human-readable name for lock ordering (e.g., "tasks", "regions").
     name: &'static str,
     /// Lock rank for deadlock prevention.
     rank: Option<LockRank>,
+    /// Priority-inheritance bookkeeping, when enabled.
+    inheritance: Option<Arc<PriorityInheritance>>,
 }
 
 // Safety: Mutex is Send/Sync if T is Send.
@@ -132,11 +155,14 @@ impl<T> Mutex<T> {
     /// Creates a new mutex in an unlocked state with the given name for lock ordering.
     #[inline]
     #[must_use]
+    #[track_caller]
     pub fn with_name(name: &'static str, value: T) -> Self {
         let rank = lock_ordering::rank_for_lock_name(name);
         Self {
             data: UnsafeCell::new(value),
             poisoned: AtomicBool::new(false),
+            poisoning: true,
+            site: Location::caller(),
             state: ParkingMutex::new(MutexState {
                 locked: false,
                 waiters: WaiterChain::new(),
@@ -144,19 +170,67 @@ impl<T> Mutex<T> {
             }),
             name,
             rank,
+            inheritance: None,
         }
     }
 
+    /// Creates a new named mutex whose holder inherits the priority of its
+    /// highest-priority waiter until the guard is dropped.
+    ///
+    /// Donations are forwarded through at most
+    /// [`DEFAULT_MAX_INHERITANCE_DEPTH`](crate::sync::DEFAULT_MAX_INHERITANCE_DEPTH)
+    /// nested inheriting locks.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn with_priority_inheritance(name: &'static str, value: T) -> Self {
+        Self::with_priority_inheritance_depth(
+            name,
+            value,
+            crate::sync::DEFAULT_MAX_INHERITANCE_DEPTH,
+        )
+    }
+
+    /// Like [`with_priority_inheritance`](Self::with_priority_inheritance),
+    /// forwarding donations through at most `max_depth` nested inheriting
+    /// locks (minimum 1).
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn with_priority_inheritance_depth(
+        name: &'static str,
+        value: T,
+        max_depth: usize,
+    ) -> Self {
+        let mut mutex = Self::with_name(name, value);
+        mutex.inheritance = Some(Arc::new(PriorityInheritance::new(max_depth)));
+        mutex
+    }
+
     /// Creates a new mutex in an unlocked state with default naming.
     ///
     /// Note: For proper deadlock prevention, prefer `with_name()` to specify
     /// the mutex's role in the lock hierarchy (e.g., "tasks", "regions").
     #[inline]
     #[must_use]
+    #[track_caller]
     pub fn new(value: T) -> Self {
         Self::with_name("unknown", value)
     }
 
+    /// Creates a new mutex that a panicking holder does not poison.
+    ///
+    /// Use for state whose invariants a panic cannot break, such as counters
+    /// or caches that are valid after any partial update.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn new_unpoisoned(value: T) -> Self {
+        let mut mutex = Self::new(value);
+        mutex.poisoning = false;
+        mutex
+    }
+
     /// Returns true if the mutex is poisoned.
     #[inline]
     #[must_use]
@@ -164,6 +238,23 @@ impl<T> Mutex<T> {
         self.poisoned.load(Ordering::Acquire)
     }
 
+    /// Clears the poisoned flag so ordinary acquisition succeeds again.
+    ///
+    /// Call this once the protected value's invariants have been restored,
+    /// typically while holding the guard obtained from
+    /// [`lock_recoverable`](Self::lock_recoverable).
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release);
+    }
+
+    /// Returns the source location that constructed this mutex.
+    #[inline]
+    #[must_use]
+    pub fn construction_site(&self) -> &'static Location<'static> {
+        self.site
+    }
+
     /// Returns true if the mutex is currently locked.
     #[inline]
     #[must_use]
@@ -178,18 +269,46 @@ impl<T> Mutex<T> {
         self.state.lock().waiters.len()
     }
 
+    /// Returns true if this mutex applies priority inheritance.
+    #[inline]
+    #[must_use]
+    pub fn has_priority_inheritance(&self) -> bool {
+        self.inheritance.is_some()
+    }
+
     /// Acquires the mutex asynchronously.
     #[inline]
     pub fn lock<'a, 'b, Caps>(&'a self, cx: &'b Cx<Caps>) -> LockFuture<'a, 'b, T, Caps> {
+        crate::runtime::chaos::perturb(crate::runtime::chaos::MUTEX_LOCK);
         LockFuture {
             mutex: self,
             cx,
             waiter_id: None,
+            inheritance_wait: None,
             deadline_sleep: None,
+            recover_poison: false,
             completed: false,
         }
     }
 
+    /// Acquires the mutex asynchronously, handing the guard back even when
+    /// the mutex is poisoned.
+    ///
+    /// A poisoned mutex yields `Ok(Err(PoisonError))` holding the guard:
+    /// repair the value through
+    /// [`PoisonError::into_inner_unchecked`] and call
+    /// [`clear_poison`](Self::clear_poison). Cancellation still fails with
+    /// [`LockError::Cancelled`].
+    #[inline]
+    pub fn lock_recoverable<'a, 'b, Caps>(
+        &'a self,
+        cx: &'b Cx<Caps>,
+    ) -> RecoverableLockFuture<'a, 'b, T, Caps> {
+        let mut inner = self.lock(cx);
+        inner.recover_poison = true;
+        RecoverableLockFuture { inner }
+    }
+
     /// Acquires the mutex asynchronously until the given deadline.
     ///
     /// Returns [`LockError::TimedOut`] if the deadline elapses before the lock
@@ -207,10 +326,12 @@ impl<T> Mutex<T> {
             mutex: self,
             cx,
             waiter_id: None,
+            inheritance_wait: None,
             deadline_sleep: Some(cx.timer_driver().map_or_else(
                 || Sleep::new(deadline),
                 |timer| Sleep::with_timer_driver(deadline, timer),
             )),
+            recover_poison: false,
             completed: false,
         }
     }
@@ -261,6 +382,9 @@ impl<T> Mutex<T> {
         if let Some(rank) = self.rank {
             lock_ordering::record_acquire(self.name, rank);
         }
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.acquired_by_current();
+        }
 
         Ok(MutexGuard {
             mutex: self,
@@ -317,9 +441,17 @@ impl<T> Mutex<T> {
         Ok(self.data.into_inner())
     }
 
+    /// Sets the poisoned flag, returning whether it was clear before.
     #[inline]
-    fn poison(&self) {
-        self.poisoned.store(true, Ordering::Release);
+    fn poison(&self) -> bool {
+        !self.poisoned.swap(true, Ordering::AcqRel)
+    }
+
+    /// Poisons the mutex on behalf of a guard dropped during a panic.
+    fn poison_on_panic(&self) {
+        if self.poisoning && self.poison() {
+            LockPoisonEvent::capture("mutex", self.name, self.site).emit();
+        }
     }
 
     /// Marks the mutex poisoned for tests and fuzz harnesses that need to model
@@ -328,11 +460,16 @@ impl<T> Mutex<T> {
     #[doc(hidden)]
     #[inline]
     pub fn poison_for_testing(&self) {
-        self.poison();
+        let _ = self.poison();
     }
 
     #[inline]
     fn unlock(&self) {
+        // Withdraw inherited priority while still locked so the next owner's
+        // donation cannot be cleared by this release.
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.released();
+        }
         // Extract the waker to wake outside the lock to prevent deadlocks.
         // Waking while holding the lock can cause priority inversion or deadlock
         // if the woken task tries to acquire another mutex.
@@ -357,6 +494,7 @@ impl<T> Mutex<T> {
 
 impl<T: Default> Default for Mutex<T> {
     #[inline]
+    #[track_caller]
     fn default() -> Self {
         Self::new(T::default())
     }
@@ -369,7 +507,11 @@ pub struct LockFuture<'a, 'b, T, Caps = crate::cx::cap::All> {
     /// Slab index of this waiter's slot in the parent mutex's
     /// `WaiterChain` (br-asupersync-wlf0xh).
     waiter_id: Option<crate::sync::waiter::WaiterId>,
+    /// Donation registered with an inheriting mutex while queued.
+    inheritance_wait: Option<InheritanceWait>,
     deadline_sleep: Option<Sleep>,
+    /// Acquire a poisoned mutex instead of failing with `Poisoned`.
+    recover_poison: bool,
     completed: bool,
 }
 
@@ -396,8 +538,19 @@ impl<T, Caps> LockFuture<'_, '_, T, Caps> {
         }
     }
 
+    /// Publishes this task as the holder of an inheriting mutex.
+    fn inherit_on_acquire(&mut self, context: &Context<'_>) {
+        if let Some(inheritance) = &self.mutex.inheritance {
+            inheritance.end_wait(&mut self.inheritance_wait);
+            inheritance.acquired(self.cx, context.waker());
+        }
+    }
+
     #[inline]
     fn cleanup_waiter(&mut self) {
+        if let Some(inheritance) = &self.mutex.inheritance {
+            inheritance.end_wait(&mut self.inheritance_wait);
+        }
         if let Some(waiter_id) = self.waiter_id.take() {
             let (waker_to_wake, retired_waker) = {
                 let mut state = self.mutex.state.lock();
@@ -471,7 +624,7 @@ impl<'a, T, Caps> Future for LockFuture<'a, '_, T, Caps> {
         loop {
             let mut state = self.mutex.state.lock();
 
-            if self.mutex.is_poisoned() {
+            if self.mutex.is_poisoned() && !self.recover_poison {
                 self.completed = true;
                 drop(state);
                 self.cleanup_waiter();
@@ -495,6 +648,8 @@ impl<'a, T, Caps> Future for LockFuture<'a, '_, T, Caps> {
                         if let Some(rank) = self.mutex.rank {
                             lock_ordering::record_acquire(self.mutex.name, rank);
                         }
+                        drop(state);
+                        self.inherit_on_acquire(context);
 
                         return Poll::Ready(Ok(MutexGuard {
                             mutex: self.mutex,
@@ -537,6 +692,8 @@ impl<'a, T, Caps> Future for LockFuture<'a, '_, T, Caps> {
                 if let Some(rank) = self.mutex.rank {
                     lock_ordering::record_acquire(self.mutex.name, rank);
                 }
+                drop(state);
+                self.inherit_on_acquire(context);
 
                 return Poll::Ready(Ok(MutexGuard {
                     mutex: self.mutex,
@@ -580,6 +737,14 @@ impl<'a, T, Caps> Future for LockFuture<'a, '_, T, Caps> {
             break;
         }
 
+        // Donate this task's priority to the holder once per wait.
+        let (mutex, cx) = (self.mutex, self.cx);
+        if self.inheritance_wait.is_none()
+            && let Some(inheritance) = &mutex.inheritance
+        {
+            self.inheritance_wait = Some(inheritance.begin_wait(cx));
+        }
+
         if let Some(deadline) = self.poll_deadline_sleep(context) {
             self.completed = true;
             self.cleanup_waiter();
@@ -596,6 +761,30 @@ impl<T, Caps> Drop for LockFuture<'_, '_, T, Caps> {
     }
 }
 
+/// Future returned by `Mutex::lock_recoverable`.
+pub struct RecoverableLockFuture<'a, 'b, T, Caps = crate::cx::cap::All> {
+    inner: LockFuture<'a, 'b, T, Caps>,
+}
+
+impl<'a, T, Caps> Future for RecoverableLockFuture<'a, '_, T, Caps> {
+    type Output = Result<LockResult<MutexGuard<'a, T>>, LockError>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let guard = match Pin::new(&mut self.inner).poll(context) {
+            Poll::Ready(Ok(guard)) => guard,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let mutex = guard.mutex;
+        if mutex.is_poisoned() {
+            let poisoned = PoisonError::new(guard, mutex.name, mutex.site);
+            return Poll::Ready(Ok(Err(poisoned)));
+        }
+        Poll::Ready(Ok(Ok(guard)))
+    }
+}
+
 /// A guard that releases the mutex when dropped.
 ///
 /// A borrowed guard is deliberately not [`Send`]: releasing a ranked mutex
@@ -648,7 +837,7 @@ impl<T> DerefMut for MutexGuard<'_, T> {
 impl<T> Drop for MutexGuard<'_, T> {
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.mutex.poison();
+            self.mutex.poison_on_panic();
         }
         self.mutex.unlock();
 
@@ -737,7 +926,7 @@ impl<T, U: ?Sized> DerefMut for MappedMutexGuard<'_, T, U> {
 impl<T, U: ?Sized> Drop for MappedMutexGuard<'_, T, U> {
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.mutex.poison();
+            self.mutex.poison_on_panic();
         }
         self.mutex.unlock();
 
@@ -832,6 +1021,9 @@ impl<T> OwnedMutexGuard<T> {
         if let Some(rank) = mutex.rank {
             lock_ordering::record_acquire(mutex.name, rank);
         }
+        if let Some(inheritance) = &mutex.inheritance {
+            inheritance.acquired_by_current();
+        }
 
         Ok(Self { mutex })
     }
@@ -892,7 +1084,7 @@ impl<T> DerefMut for OwnedMutexGuard<T> {
 impl<T> Drop for OwnedMutexGuard<T> {
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.mutex.poison();
+            self.mutex.poison_on_panic();
         }
         self.mutex.unlock();
 
@@ -941,7 +1133,7 @@ impl<T, U: ?Sized> DerefMut for OwnedMappedMutexGuard<T, U> {
 impl<T, U: ?Sized> Drop for OwnedMappedMutexGuard<T, U> {
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.mutex.poison();
+            self.mutex.poison_on_panic();
         }
         self.mutex.unlock();
 
@@ -1003,6 +1195,8 @@ mod tests {
     )]
     use super::*;
     use crate::conformance::{ConformanceTarget, LabRuntimeTarget, TestConfig};
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::observability::{LogCollector, LogEntry, LogLevel};
     use crate::runtime::yield_now;
     use crate::test_utils::init_test_logging;
     use crate::time::{TimerDriverHandle, VirtualClock};
@@ -4858,6 +5052,132 @@ mod tests {
         crate::test_complete!("audit_mutex_guard_map_field_projection_multi_field_struct");
     }
 
+    fn poison_events(collector: &LogCollector) -> Vec<LogEntry> {
+        collector
+            .peek()
+            .into_iter()
+            .filter(|entry| entry.message() == LockPoisonEvent::MESSAGE)
+            .collect()
+    }
+
+    #[test]
+    fn panic_while_held_poisons_and_logs_task_and_site_under_lab() {
+        init_test("panic_while_held_poisons_and_logs_task_and_site_under_lab");
+        let mut runtime = LabRuntime::new(LabConfig::new(0x9015));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let mutex = Arc::new(Mutex::with_name("ledger", 10_u32));
+        let site_line = line!() - 1;
+        let collector = LogCollector::new(16).with_min_level(LogLevel::Debug);
+
+        let (holder_mutex, holder_collector) = (Arc::clone(&mutex), collector.clone());
+        let (holder, _handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("task cx");
+                cx.set_task_type("ledger_writer");
+                cx.set_log_collector(holder_collector);
+                let mut balance = holder_mutex.lock(&cx).await.expect("holder locks");
+                *balance -= 3;
+                if *balance < 10 {
+                    panic!("transfer interrupted half-way");
+                }
+            })
+            .expect("create holder");
+        runtime.scheduler.lock().schedule(holder, 0);
+        runtime.run_until_quiescent();
+
+        crate::assert_with_log!(
+            mutex.is_poisoned(),
+            "panicking holder poisons the mutex",
+            true,
+            mutex.is_poisoned()
+        );
+        crate::assert_with_log!(
+            !mutex.is_locked(),
+            "unwind releases the lock",
+            false,
+            mutex.is_locked()
+        );
+        assert!(matches!(mutex.try_lock(), Err(TryLockError::Poisoned)));
+
+        let events = poison_events(&collector);
+        assert_eq!(events.len(), 1, "{events:?}");
+        let field = |key: &str| events[0].get_field(key).map(str::to_owned);
+        let site = mutex.construction_site();
+        assert_eq!(site.line(), site_line);
+        assert_eq!(field("primitive").as_deref(), Some("mutex"));
+        assert_eq!(field("lock").as_deref(), Some("ledger"));
+        assert_eq!(field("site"), Some(site.to_string()));
+        assert_eq!(field("task"), Some(holder.to_string()));
+        assert_eq!(field("task_label").as_deref(), Some("ledger_writer"));
+        crate::test_complete!("panic_while_held_poisons_and_logs_task_and_site_under_lab");
+    }
+
+    #[test]
+    fn recoverable_lock_repairs_value_and_clear_poison_readmits_acquirers() {
+        init_test("recoverable_lock_repairs_value_and_clear_poison_readmits_acquirers");
+        let cx = test_cx();
+        let mutex = Mutex::with_name("ledger", 10_u32);
+
+        let healthy = block_on(mutex.lock_recoverable(&cx)).expect("not cancelled");
+        assert_eq!(*healthy.expect("healthy mutex is not poisoned"), 10);
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut balance = mutex.try_lock().expect("unlocked");
+            *balance = 7;
+            panic!("transfer interrupted half-way");
+        }));
+        assert!(panicked.is_err());
+        assert!(matches!(block_on(mutex.lock(&cx)), Err(LockError::Poisoned)));
+
+        let recovered = block_on(mutex.lock_recoverable(&cx)).expect("not cancelled");
+        let poisoned = recovered.expect_err("mutex is poisoned");
+        assert_eq!(poisoned.lock_name(), "ledger");
+        assert_eq!(poisoned.construction_site(), mutex.construction_site());
+        assert_eq!(**poisoned.get_ref(), 7);
+        let mut balance = poisoned.into_inner_unchecked();
+        *balance = 10;
+        mutex.clear_poison();
+        drop(balance);
+
+        crate::assert_with_log!(
+            !mutex.is_poisoned(),
+            "clear_poison lifts the poison",
+            false,
+            mutex.is_poisoned()
+        );
+        let balance = block_on(mutex.lock(&cx)).expect("ordinary lock succeeds again");
+        assert_eq!(*balance, 10);
+        crate::test_complete!("recoverable_lock_repairs_value_and_clear_poison_readmits_acquirers");
+    }
+
+    #[test]
+    fn unpoisoned_mutex_survives_holder_panic_without_event() {
+        init_test("unpoisoned_mutex_survives_holder_panic_without_event");
+        let collector = LogCollector::new(16).with_min_level(LogLevel::Debug);
+        let observer = test_cx();
+        observer.set_log_collector(collector.clone());
+        let _current = Cx::set_current(Some(observer));
+        let hits = Mutex::new_unpoisoned(0_u64);
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut hits = hits.try_lock().expect("unlocked");
+            *hits += 1;
+            panic!("request handler failed");
+        }));
+        assert!(panicked.is_err());
+
+        crate::assert_with_log!(
+            !hits.is_poisoned(),
+            "opt-out mutex is not poisoned",
+            false,
+            hits.is_poisoned()
+        );
+        assert_eq!(*hits.try_lock().expect("lock still admits acquirers"), 1);
+        assert!(poison_events(&collector).is_empty());
+        crate::test_complete!("unpoisoned_mutex_survives_holder_panic_without_event");
+    }
+
     #[derive(Debug)]
     struct TestStruct {
         field_a: i32,
@@ -420,7 +420,7 @@ impl Notify {
         let new_generation = self.generation.fetch_add(1, Ordering::Release) + 1;
 
         // Collect all wakers (SmallVec avoids heap allocation for ≤8 waiters).
-        let wakers: SmallVec<[Waker; 8]> = {
+        let mut wakers: SmallVec<[Waker; 8]> = {
             let mut waiters = self.waiters.lock();
 
             let wakers: SmallVec<[Waker; 8]> = waiters
@@ -447,6 +447,7 @@ impl Notify {
             waiters.active -= wakers.len();
             wakers
         };
+        crate::runtime::chaos::shuffle(&mut wakers);
 
         // Wake all. Isolate each detached wake so one hostile safe Waker cannot
         // strand the later waiters: they have already had their wakers taken and
@@ -0,0 +1,174 @@
+//! Poisoning for locks whose holder panicked.
+//!
+//! A [`Mutex`](super::Mutex), or an [`RwLock`](super::RwLock) whose *write*
+//! guard is dropped during a panic, is marked poisoned: the protected value
+//! may be half-way through an update. Read guards never poison, since a reader
+//! cannot leave a partial write behind.
+//!
+//! Ordinary acquisition of a poisoned lock fails with its `Poisoned` error.
+//! [`Mutex::lock_recoverable`](super::Mutex::lock_recoverable) instead hands
+//! the guard back inside a [`PoisonError`], so the caller can inspect and
+//! repair the value and then call `clear_poison` to re-admit ordinary
+//! acquirers. Locks protecting state that a panic cannot corrupt opt out with
+//! [`Mutex::new_unpoisoned`](super::Mutex::new_unpoisoned).
+//!
+//! # Events
+//!
+//! Every transition to poisoned is logged through the panicking task's
+//! context as a structured [`LockPoisonEvent`] naming the lock, the site that
+//! constructed it, and the task, and is mirrored to `tracing` at warn level.
+//! A supervisor configured with
+//! [`PoisonPolicy::RestartSubtree`](crate::supervision::PoisonPolicy::RestartSubtree)
+//! restarts the children sharing the lock once one of them detects poison.
+
+use std::fmt;
+use std::panic::Location;
+
+use crate::cx::Cx;
+use crate::observability::LogEntry;
+use crate::types::TaskId;
+
+/// Result of a poison-aware acquisition: the guard, or the guard wrapped in
+/// a [`PoisonError`] when the lock is poisoned.
+pub type LockResult<G> = Result<G, PoisonError<G>>;
+
+/// A lock was acquired while poisoned.
+///
+/// Holds the acquired guard. The lock stays poisoned until its owner calls
+/// `clear_poison`, so dropping this error without repairing anything leaves
+/// later acquirers failing too.
+pub struct PoisonError<G> {
+    guard: G,
+    lock: &'static str,
+    site: &'static Location<'static>,
+}
+
+impl<G> PoisonError<G> {
+    pub(crate) const fn new(
+        guard: G,
+        lock: &'static str,
+        site: &'static Location<'static>,
+    ) -> Self {
+        Self { guard, lock, site }
+    }
+
+    /// Returns the guard without checking the protected value.
+    ///
+    /// The value is in whatever state the panicking holder left it. Restore
+    /// its invariants before calling `clear_poison`.
+    #[must_use]
+    pub fn into_inner_unchecked(self) -> G {
+        self.guard
+    }
+
+    /// Returns a reference to the guard.
+    #[must_use]
+    pub const fn get_ref(&self) -> &G {
+        &self.guard
+    }
+
+    /// Returns a mutable reference to the guard.
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut G {
+        &mut self.guard
+    }
+
+    /// Name the poisoned lock was constructed with.
+    #[must_use]
+    pub const fn lock_name(&self) -> &'static str {
+        self.lock
+    }
+
+    /// Source location that constructed the poisoned lock.
+    #[must_use]
+    pub const fn construction_site(&self) -> &'static Location<'static> {
+        self.site
+    }
+}
+
+impl<G> fmt::Debug for PoisonError<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoisonError")
+            .field("lock", &self.lock)
+            .field("site", &self.site)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> fmt::Display for PoisonError<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lock `{}` constructed at {} is poisoned", self.lock, self.site)
+    }
+}
+
+impl<G> std::error::Error for PoisonError<G> {}
+
+/// Structured record of a lock becoming poisoned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockPoisonEvent {
+    /// Lock kind, `mutex` or `rwlock`.
+    pub primitive: &'static str,
+    /// Name the lock was constructed with.
+    pub lock: &'static str,
+    /// Source location that constructed the lock.
+    pub site: &'static Location<'static>,
+    /// Task whose panic poisoned the lock, when it ran under a task context.
+    pub task: Option<TaskId>,
+    /// The panicking task's type label, see
+    /// [`Cx::set_task_type`](crate::cx::Cx::set_task_type).
+    pub task_label: Option<String>,
+}
+
+impl LockPoisonEvent {
+    /// Message shared by every poison log entry.
+    pub const MESSAGE: &'static str = "lock poisoned";
+    /// Target shared by every poison log entry.
+    pub const TARGET: &'static str = "asupersync::sync::poison";
+
+    /// Describes `lock` being poisoned by the ambient task.
+    pub(crate) fn capture(
+        primitive: &'static str,
+        lock: &'static str,
+        site: &'static Location<'static>,
+    ) -> Self {
+        let (task, task_label) = Cx::with_current(|cx| (Some(cx.task_id()), cx.task_type()))
+            .unwrap_or_default();
+        Self {
+            primitive,
+            lock,
+            site,
+            task,
+            task_label,
+        }
+    }
+
+    /// Renders the event as a structured log entry.
+    #[must_use]
+    pub fn to_log_entry(&self) -> LogEntry {
+        let mut entry = LogEntry::warn(Self::MESSAGE)
+            .with_target(Self::TARGET)
+            .with_field("primitive", self.primitive)
+            .with_field("lock", self.lock)
+            .with_field("site", self.site.to_string());
+        if let Some(task) = self.task {
+            entry = entry.with_field("task", task.to_string());
+        }
+        if let Some(label) = &self.task_label {
+            entry = entry.with_field("task_label", label.as_str());
+        }
+        entry
+    }
+
+    pub(crate) fn emit(&self) {
+        crate::tracing_compat::warn!(
+            primitive = self.primitive,
+            lock = self.lock,
+            site = %self.site,
+            task = ?self.task,
+            task_label = ?self.task_label,
+            "lock poisoned"
+        );
+        let entry = self.to_log_entry();
+        let _ = Cx::with_current(|cx| cx.log(entry));
+    }
+}
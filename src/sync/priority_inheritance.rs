@@ -0,0 +1,805 @@
+//! Priority inheritance for async locks.
+//!
+//! A low-priority task holding a lock that a high-priority task waits on
+//! inherits the waiter's priority until it releases the lock. Without this,
+//! medium-priority work can keep the holder off the scheduler indefinitely
+//! and, through it, the high-priority waiter (priority inversion).
+//!
+//! Inheritance is opt-in per lock, see
+//! [`Mutex::with_priority_inheritance`](crate::sync::Mutex::with_priority_inheritance)
+//! and
+//! [`RwLock::with_priority_inheritance`](crate::sync::RwLock::with_priority_inheritance)
+//! (write side only). Every task context carries a `TaskPriorityNode` that
+//! records one donation per inheriting lock the task holds; the task's
+//! inherited priority is the largest donation, and schedulers run it at
+//! `max(budget.priority, inherited)`.
+//!
+//! # Chaining
+//!
+//! When a boosted holder is itself queued on another inheriting lock, its new
+//! effective priority is forwarded to that lock's holder, and so on. Each
+//! lock bounds how many hops a donation travels; hitting the bound stops
+//! propagation and emits a [`PriorityInheritanceEventKind::DepthLimited`]
+//! event. The bound also terminates propagation around wait-for cycles.
+//!
+//! # Scheduling
+//!
+//! A boost wakes the holder's last known waker so the scheduler requeues it
+//! at the raised priority. The lab scheduler promotes an already-queued task
+//! in place, which keeps boosting deterministic under replay. The
+//! work-stealing scheduler applies the inherited priority on the holder's
+//! next global enqueue. The extra wake may be spurious for whatever the
+//! holder is awaiting, which futures already have to tolerate.
+//!
+//! # Events
+//!
+//! Every change to a holder's effective priority is logged through the
+//! ambient task context as a structured [`PriorityInheritanceEvent`] entry and
+//! mirrored to `tracing` at debug level.
+
+use parking_lot::Mutex as ParkingMutex;
+use smallvec::SmallVec;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::task::Waker;
+
+use crate::cx::Cx;
+use crate::observability::LogEntry;
+use crate::types::TaskId;
+
+/// Default bound on how many lock hops a donation is forwarded through.
+pub const DEFAULT_MAX_INHERITANCE_DEPTH: usize = 8;
+
+static NEXT_LOCK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// What happened to a lock holder's effective priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriorityInheritanceEventKind {
+    /// A waiter's donation raised the holder's effective priority.
+    Boosted,
+    /// A donation was withdrawn and the holder's effective priority fell.
+    Restored,
+    /// Propagation reached the lock's depth bound and stopped.
+    DepthLimited,
+}
+
+impl PriorityInheritanceEventKind {
+    /// Returns a stable lowercase label for logs and metrics.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Boosted => "boosted",
+            Self::Restored => "restored",
+            Self::DepthLimited => "depth_limited",
+        }
+    }
+}
+
+/// Structured record of a priority-inheritance transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityInheritanceEvent {
+    /// What happened.
+    pub kind: PriorityInheritanceEventKind,
+    /// Process-unique id of the lock that carried the donation.
+    pub lock_id: u64,
+    /// Task whose effective priority changed; for `DepthLimited`, the boosted
+    /// task whose priority was not forwarded any further.
+    pub holder: TaskId,
+    /// Holder's effective priority before the transition.
+    pub from: u8,
+    /// Holder's effective priority after the transition.
+    pub to: u8,
+    /// Lock hops between the triggering waiter and `holder`.
+    pub depth: usize,
+}
+
+impl PriorityInheritanceEvent {
+    /// Message shared by every inheritance log entry.
+    pub const MESSAGE: &'static str = "priority inheritance";
+    /// Target shared by every inheritance log entry.
+    pub const TARGET: &'static str = "asupersync::sync::priority_inheritance";
+
+    /// Renders the event as a structured log entry.
+    #[must_use]
+    pub fn to_log_entry(&self) -> LogEntry {
+        LogEntry::debug(Self::MESSAGE)
+            .with_target(Self::TARGET)
+            .with_field("kind", self.kind.as_str())
+            .with_field("lock_id", self.lock_id.to_string())
+            .with_field("holder", self.holder.to_string())
+            .with_field("from", self.from.to_string())
+            .with_field("to", self.to.to_string())
+            .with_field("depth", self.depth.to_string())
+    }
+
+    fn emit(&self) {
+        crate::tracing_compat::debug!(
+            kind = self.kind.as_str(),
+            lock_id = self.lock_id,
+            holder = %self.holder,
+            from = self.from,
+            to = self.to,
+            depth = self.depth,
+            "priority inheritance"
+        );
+        let entry = self.to_log_entry();
+        let _ = Cx::with_current(|cx| cx.log(entry));
+    }
+}
+
+/// Per-task inheritance bookkeeping, shared through the task's context.
+#[derive(Debug)]
+pub(crate) struct TaskPriorityNode {
+    task: TaskId,
+    /// Budget priority last observed when the task waited on or acquired an
+    /// inheriting lock.
+    base: AtomicU8,
+    /// Largest donation currently held, `0` when none.
+    inherited: AtomicU8,
+    links: ParkingMutex<NodeLinks>,
+}
+
+#[derive(Debug, Default)]
+struct NodeLinks {
+    /// One `(lock_id, priority)` donation per inheriting lock held.
+    donations: SmallVec<[(u64, u8); 2]>,
+    /// Inheriting lock this task is queued on, used to forward donations.
+    blocked_on: Option<Arc<PriorityInheritance>>,
+}
+
+impl TaskPriorityNode {
+    pub(crate) fn new(task: TaskId) -> Self {
+        Self {
+            task,
+            base: AtomicU8::new(0),
+            inherited: AtomicU8::new(0),
+            links: ParkingMutex::new(NodeLinks::default()),
+        }
+    }
+
+    /// Returns the priority donated by waiters, `0` when none.
+    #[inline]
+    pub(crate) fn inherited(&self) -> u8 {
+        self.inherited.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    fn effective(&self) -> u8 {
+        self.base.load(Ordering::Acquire).max(self.inherited())
+    }
+
+    /// Replaces the donation received through `lock_id`, returning the
+    /// effective priority before and after.
+    fn set_donation(&self, lock_id: u64, donation: Option<u8>) -> (u8, u8) {
+        let mut links = self.links.lock();
+        let before = self.effective();
+        links.donations.retain(|(id, _)| *id != lock_id);
+        if let Some(priority) = donation {
+            links.donations.push((lock_id, priority));
+        }
+        let inherited = links
+            .donations
+            .iter()
+            .map(|(_, priority)| *priority)
+            .max()
+            .unwrap_or(0);
+        self.inherited.store(inherited, Ordering::Release);
+        drop(links);
+        (before, self.effective())
+    }
+
+    fn blocked_on(&self) -> Option<Arc<PriorityInheritance>> {
+        self.links.lock().blocked_on.clone()
+    }
+
+    /// Swaps the lock this task is queued on; the caller drops the previous
+    /// value after the node lock is released.
+    fn replace_blocked_on(
+        &self,
+        lock: Option<Arc<PriorityInheritance>>,
+    ) -> Option<Arc<PriorityInheritance>> {
+        std::mem::replace(&mut self.links.lock().blocked_on, lock)
+    }
+}
+
+/// Identifies one queued waiter; returned by [`PriorityInheritance::begin_wait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InheritanceWait(u64);
+
+/// Inheritance state attached to a single lock.
+#[derive(Debug)]
+pub(crate) struct PriorityInheritance {
+    id: u64,
+    max_depth: usize,
+    state: ParkingMutex<InheritanceState>,
+}
+
+#[derive(Debug, Default)]
+struct InheritanceState {
+    holder: Option<Holder>,
+    waiters: SmallVec<[(u64, Arc<TaskPriorityNode>); 4]>,
+    next_wait: u64,
+}
+
+#[derive(Debug)]
+struct Holder {
+    node: Arc<TaskPriorityNode>,
+    /// Shared so it can be cloned under the state lock without running a
+    /// user-controlled `RawWaker::clone`.
+    waker: Option<Arc<Waker>>,
+}
+
+/// Returns the task node and budget priority behind `cx`.
+fn identity<Caps>(cx: &Cx<Caps>) -> (Arc<TaskPriorityNode>, u8) {
+    let inner = cx.inner.read();
+    (Arc::clone(&inner.priority_inheritance), inner.budget.priority)
+}
+
+impl PriorityInheritance {
+    pub(crate) fn new(max_depth: usize) -> Self {
+        Self {
+            id: NEXT_LOCK_ID.fetch_add(1, Ordering::Relaxed),
+            max_depth: max_depth.max(1),
+            state: ParkingMutex::new(InheritanceState::default()),
+        }
+    }
+
+    /// Queues the task behind `cx` as a donor and boosts the holder chain.
+    pub(crate) fn begin_wait<Caps>(self: &Arc<Self>, cx: &Cx<Caps>) -> InheritanceWait {
+        let (node, base) = identity(cx);
+        node.base.store(base, Ordering::Release);
+        drop(node.replace_blocked_on(Some(Arc::clone(self))));
+        let wait = {
+            let mut state = self.state.lock();
+            let wait = state.next_wait;
+            state.next_wait = state.next_wait.wrapping_add(1);
+            state.waiters.push((wait, node));
+            InheritanceWait(wait)
+        };
+        self.propagate();
+        wait
+    }
+
+    /// Withdraws a waiter's donation, if it is still queued.
+    pub(crate) fn end_wait(&self, wait: &mut Option<InheritanceWait>) {
+        let Some(InheritanceWait(wait)) = wait.take() else {
+            return;
+        };
+        let node = {
+            let mut state = self.state.lock();
+            state
+                .waiters
+                .iter()
+                .position(|(key, _)| *key == wait)
+                .map(|index| state.waiters.swap_remove(index).1)
+        };
+        if let Some(node) = node {
+            drop(node.replace_blocked_on(None));
+            self.propagate();
+        }
+    }
+
+    /// Records the task behind `cx` as the holder and donates the priority of
+    /// any waiters still queued.
+    pub(crate) fn acquired<Caps>(&self, cx: &Cx<Caps>, waker: &Waker) {
+        let identity = identity(cx);
+        self.set_holder(Some(identity), Some(Arc::new(waker.clone())));
+    }
+
+    /// Like [`acquired`](Self::acquired) for non-blocking acquisition, using
+    /// the ambient task context when there is one.
+    pub(crate) fn acquired_by_current(&self) {
+        self.set_holder(Cx::with_current(identity), None);
+    }
+
+    fn set_holder(
+        &self,
+        identity: Option<(Arc<TaskPriorityNode>, u8)>,
+        waker: Option<Arc<Waker>>,
+    ) {
+        let Some((node, base)) = identity else {
+            return;
+        };
+        node.base.store(base, Ordering::Release);
+        let previous = self.state.lock().holder.replace(Holder { node, waker });
+        drop(previous);
+        self.propagate();
+    }
+
+    /// Clears the holder and withdraws everything it inherited through this
+    /// lock. Must run before the lock is handed to the next owner.
+    pub(crate) fn released(&self) {
+        let Some(holder) = self.state.lock().holder.take() else {
+            return;
+        };
+        let (from, to) = holder.node.set_donation(self.id, None);
+        if to < from {
+            PriorityInheritanceEvent {
+                kind: PriorityInheritanceEventKind::Restored,
+                lock_id: self.id,
+                holder: holder.node.task,
+                from,
+                to,
+                depth: 0,
+            }
+            .emit();
+            if let Some(next) = holder.node.blocked_on() {
+                next.propagate_from(1, self.max_depth);
+            }
+        }
+    }
+
+    fn propagate(&self) {
+        self.propagate_from(0, self.max_depth);
+    }
+
+    /// Refreshes the donation to this lock's holder and forwards any change
+    /// along the holder's own wait, at most `max_depth` hops in total.
+    fn propagate_from(&self, mut depth: usize, max_depth: usize) {
+        let Some(mut next) = self.refresh_holder(depth) else {
+            return;
+        };
+        loop {
+            let (holder, lock) = next;
+            depth += 1;
+            if depth >= max_depth {
+                let priority = holder.effective();
+                PriorityInheritanceEvent {
+                    kind: PriorityInheritanceEventKind::DepthLimited,
+                    lock_id: lock.id,
+                    holder: holder.task,
+                    from: priority,
+                    to: priority,
+                    depth,
+                }
+                .emit();
+                return;
+            }
+            let Some(forward) = lock.refresh_holder(depth) else {
+                return;
+            };
+            next = forward;
+        }
+    }
+
+    /// Sets the holder's donation to the highest waiter priority. Returns the
+    /// holder and the lock it is queued on when its effective priority
+    /// changed and that lock needs refreshing too.
+    fn refresh_holder(
+        &self,
+        depth: usize,
+    ) -> Option<(Arc<TaskPriorityNode>, Arc<PriorityInheritance>)> {
+        let (node, waker, donation) = {
+            let state = self.state.lock();
+            let holder = state.holder.as_ref()?;
+            let donation = state.waiters.iter().map(|(_, node)| node.effective()).max();
+            (Arc::clone(&holder.node), holder.waker.clone(), donation)
+        };
+        let (from, to) = node.set_donation(self.id, donation);
+        if from == to {
+            return None;
+        }
+        let kind = if to > from {
+            PriorityInheritanceEventKind::Boosted
+        } else {
+            PriorityInheritanceEventKind::Restored
+        };
+        PriorityInheritanceEvent {
+            kind,
+            lock_id: self.id,
+            holder: node.task,
+            from,
+            to,
+            depth,
+        }
+        .emit();
+        if kind == PriorityInheritanceEventKind::Boosted
+            && let Some(waker) = waker
+        {
+            waker.wake_by_ref();
+        }
+        let lock = node.blocked_on()?;
+        Some((node, lock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::observability::{LogCollector, LogLevel};
+    use crate::runtime::yield_now;
+    use crate::sync::{Mutex, RwLock};
+    use crate::types::{Budget, RegionId};
+    use futures_lite::future::block_on;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::AtomicBool;
+    use std::task::Context;
+
+    const LOW: u8 = 10;
+    const MEDIUM: u8 = 100;
+    const HIGH: u8 = 200;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn task_cx(task: u32, priority: u8) -> Cx {
+        Cx::new(
+            RegionId::new_for_test(0, 1),
+            TaskId::new_for_test(task, 0),
+            Budget::INFINITE.with_priority(priority),
+        )
+    }
+
+    fn inherited(cx: &Cx) -> u8 {
+        cx.inner.read().priority_inheritance.inherited()
+    }
+
+    fn poll_pending<F: Future + Unpin>(future: &mut F) {
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(
+            Pin::new(future).poll(&mut context).is_pending(),
+            "waiter should queue behind the holder"
+        );
+    }
+
+    /// Installs an ambient context whose collector captures emitted events.
+    fn observe() -> (LogCollector, crate::cx::cx::CurrentCxGuard) {
+        let collector = LogCollector::new(64).with_min_level(LogLevel::Debug);
+        let observer = task_cx(99, 0);
+        observer.set_log_collector(collector.clone());
+        (collector, Cx::set_current(Some(observer)))
+    }
+
+    /// Renders captured events as `kind holder from->to @depth`.
+    fn events(collector: &LogCollector) -> Vec<String> {
+        collector
+            .peek()
+            .iter()
+            .filter(|entry| entry.message() == PriorityInheritanceEvent::MESSAGE)
+            .map(|entry| {
+                let field = |key: &str| entry.get_field(key).expect("event field");
+                format!(
+                    "{} {} {}->{} @{}",
+                    field("kind"),
+                    field("holder"),
+                    field("from"),
+                    field("to"),
+                    field("depth")
+                )
+            })
+            .collect()
+    }
+
+    fn task_label(task: u32) -> String {
+        TaskId::new_for_test(task, 0).to_string()
+    }
+
+    #[test]
+    fn holder_is_boosted_while_waited_on_and_restored_on_release() {
+        init_test("holder_is_boosted_while_waited_on_and_restored_on_release");
+        let (collector, _current) = observe();
+        let mutex = Mutex::with_priority_inheritance("pi_boost", 0_u32);
+        let low = task_cx(1, LOW);
+        let high = task_cx(2, HIGH);
+
+        let guard = block_on(mutex.lock(&low)).expect("low locks");
+        assert_eq!(inherited(&low), 0);
+
+        let mut waiter = mutex.lock(&high);
+        poll_pending(&mut waiter);
+        crate::assert_with_log!(
+            inherited(&low) == HIGH,
+            "holder inherits waiter priority",
+            HIGH,
+            inherited(&low)
+        );
+
+        drop(guard);
+        crate::assert_with_log!(
+            inherited(&low) == 0,
+            "release restores base priority",
+            0,
+            inherited(&low)
+        );
+        drop(waiter);
+
+        let low_label = task_label(1);
+        assert_eq!(
+            events(&collector),
+            vec![
+                format!("boosted {low_label} {LOW}->{HIGH} @0"),
+                format!("restored {low_label} {HIGH}->{LOW} @0"),
+            ]
+        );
+        crate::test_complete!("holder_is_boosted_while_waited_on_and_restored_on_release");
+    }
+
+    #[test]
+    fn cancelled_waiter_withdraws_its_donation() {
+        init_test("cancelled_waiter_withdraws_its_donation");
+        let mutex = Mutex::with_priority_inheritance("pi_cancel", ());
+        let low = task_cx(1, LOW);
+        let medium = task_cx(2, MEDIUM);
+        let high = task_cx(3, HIGH);
+        let _guard = block_on(mutex.lock(&low)).expect("low locks");
+
+        let mut medium_waiter = mutex.lock(&medium);
+        poll_pending(&mut medium_waiter);
+        let mut high_waiter = mutex.lock(&high);
+        poll_pending(&mut high_waiter);
+        assert_eq!(inherited(&low), HIGH);
+
+        drop(high_waiter);
+        crate::assert_with_log!(
+            inherited(&low) == MEDIUM,
+            "falls back to the remaining waiter",
+            MEDIUM,
+            inherited(&low)
+        );
+        drop(medium_waiter);
+        assert_eq!(inherited(&low), 0);
+        crate::test_complete!("cancelled_waiter_withdraws_its_donation");
+    }
+
+    #[test]
+    fn plain_mutex_never_boosts() {
+        init_test("plain_mutex_never_boosts");
+        let mutex = Mutex::with_name("pi_plain", ());
+        assert!(!mutex.has_priority_inheritance());
+        let low = task_cx(1, LOW);
+        let high = task_cx(2, HIGH);
+        let _guard = block_on(mutex.lock(&low)).expect("low locks");
+        let mut waiter = mutex.lock(&high);
+        poll_pending(&mut waiter);
+        assert_eq!(inherited(&low), 0);
+        crate::test_complete!("plain_mutex_never_boosts");
+    }
+
+    #[test]
+    fn boost_chains_through_nested_locks() {
+        init_test("boost_chains_through_nested_locks");
+        let (collector, _current) = observe();
+        let outer = Mutex::with_priority_inheritance("pi_outer", ());
+        let inner = Mutex::with_priority_inheritance("pi_inner", ());
+        let holder = task_cx(1, LOW);
+        let middle = task_cx(2, MEDIUM);
+        let top = task_cx(3, HIGH);
+
+        // holder owns `outer`; middle owns `inner` and waits on `outer`.
+        let _outer_guard = block_on(outer.lock(&holder)).expect("holder locks outer");
+        let _inner_guard = block_on(inner.lock(&middle)).expect("middle locks inner");
+        let mut middle_waiter = outer.lock(&middle);
+        poll_pending(&mut middle_waiter);
+        assert_eq!(inherited(&holder), MEDIUM);
+
+        // top waits on `inner`: middle is boosted, and so is the holder it waits on.
+        let mut top_waiter = inner.lock(&top);
+        poll_pending(&mut top_waiter);
+        assert_eq!(inherited(&middle), HIGH);
+        crate::assert_with_log!(
+            inherited(&holder) == HIGH,
+            "boost is forwarded one hop",
+            HIGH,
+            inherited(&holder)
+        );
+
+        drop(top_waiter);
+        assert_eq!(inherited(&middle), 0);
+        assert_eq!(inherited(&holder), MEDIUM);
+
+        let (holder_label, middle_label) = (task_label(1), task_label(2));
+        assert_eq!(
+            events(&collector),
+            vec![
+                format!("boosted {holder_label} {LOW}->{MEDIUM} @0"),
+                format!("boosted {middle_label} {MEDIUM}->{HIGH} @0"),
+                format!("boosted {holder_label} {MEDIUM}->{HIGH} @1"),
+                format!("restored {middle_label} {HIGH}->{MEDIUM} @0"),
+                format!("restored {holder_label} {HIGH}->{MEDIUM} @1"),
+            ]
+        );
+        drop(middle_waiter);
+        crate::test_complete!("boost_chains_through_nested_locks");
+    }
+
+    #[test]
+    fn depth_bound_stops_propagation() {
+        init_test("depth_bound_stops_propagation");
+        let (collector, _current) = observe();
+        let outer = Mutex::with_priority_inheritance("pi_outer_bounded", ());
+        let inner = Mutex::with_priority_inheritance_depth("pi_inner_bounded", (), 1);
+        let holder = task_cx(1, LOW);
+        let middle = task_cx(2, MEDIUM);
+        let top = task_cx(3, HIGH);
+
+        let _outer_guard = block_on(outer.lock(&holder)).expect("holder locks outer");
+        let _inner_guard = block_on(inner.lock(&middle)).expect("middle locks inner");
+        let mut middle_waiter = outer.lock(&middle);
+        poll_pending(&mut middle_waiter);
+        let mut top_waiter = inner.lock(&top);
+        poll_pending(&mut top_waiter);
+
+        assert_eq!(inherited(&middle), HIGH);
+        crate::assert_with_log!(
+            inherited(&holder) == MEDIUM,
+            "donation stops at the depth bound",
+            MEDIUM,
+            inherited(&holder)
+        );
+        let recorded = events(&collector);
+        assert_eq!(
+            recorded.last().map(String::as_str),
+            Some(format!("depth_limited {} {HIGH}->{HIGH} @1", task_label(2)).as_str())
+        );
+        drop(top_waiter);
+        drop(middle_waiter);
+        crate::test_complete!("depth_bound_stops_propagation");
+    }
+
+    #[test]
+    fn rwlock_writer_inherits_until_downgrade() {
+        init_test("rwlock_writer_inherits_until_downgrade");
+        let lock = RwLock::with_priority_inheritance("pi_rwlock", 0_u32);
+        assert!(lock.has_priority_inheritance());
+        let low = task_cx(1, LOW);
+        let high = task_cx(2, HIGH);
+
+        let guard = block_on(lock.write(&low)).expect("low writes");
+        let mut waiter = lock.write(&high);
+        poll_pending(&mut waiter);
+        assert_eq!(inherited(&low), HIGH);
+
+        let read_guard = guard.downgrade();
+        crate::assert_with_log!(
+            inherited(&low) == 0,
+            "downgrade ends exclusive ownership",
+            0,
+            inherited(&low)
+        );
+        drop(read_guard);
+        drop(waiter);
+        crate::test_complete!("rwlock_writer_inherits_until_downgrade");
+    }
+
+    const LOW_WORK: u64 = 8;
+    const MEDIUM_WORK: u64 = 64;
+
+    struct InversionRun {
+        /// Lab steps taken before the high-priority task held the lock.
+        steps: u64,
+        /// Medium-priority slices completed by then.
+        medium_progress: u64,
+        /// Boost events observed.
+        boosts: usize,
+    }
+
+    /// Low takes the lock and works while holding it; high then waits on the
+    /// lock while CPU-bound medium work is runnable.
+    fn run_inversion(inherit: bool) -> InversionRun {
+        let mut runtime = LabRuntime::new(LabConfig::new(7));
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        let mutex = Arc::new(if inherit {
+            Mutex::with_priority_inheritance("pi_lab", ())
+        } else {
+            Mutex::with_name("pi_lab", ())
+        });
+        let collector = LogCollector::new(256).with_min_level(LogLevel::Debug);
+        let observed = collector.clone();
+        let medium_progress = Arc::new(AtomicU64::new(0));
+        let high_acquired = Arc::new(AtomicBool::new(false));
+
+        let (low_mutex, low_collector) = (Arc::clone(&mutex), collector.clone());
+        let (low, _low_handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE.with_priority(LOW), async move {
+                let cx = Cx::current().expect("task cx");
+                cx.set_log_collector(low_collector);
+                let _guard = low_mutex.lock(&cx).await.expect("low locks");
+                for _ in 0..LOW_WORK {
+                    yield_now().await;
+                }
+            })
+            .expect("create low");
+        runtime.scheduler.lock().schedule(low, LOW);
+        runtime.step_for_test();
+        assert!(mutex.is_locked(), "low holds the lock before contention");
+
+        let (high_mutex, high_flag) = (Arc::clone(&mutex), Arc::clone(&high_acquired));
+        let (high, _high_handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE.with_priority(HIGH), async move {
+                let cx = Cx::current().expect("task cx");
+                cx.set_log_collector(collector);
+                let _guard = high_mutex.lock(&cx).await.expect("high locks");
+                high_flag.store(true, Ordering::SeqCst);
+            })
+            .expect("create high");
+        let progress = Arc::clone(&medium_progress);
+        let (medium, _medium_handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE.with_priority(MEDIUM), async move {
+                for _ in 0..MEDIUM_WORK {
+                    progress.fetch_add(1, Ordering::SeqCst);
+                    yield_now().await;
+                }
+            })
+            .expect("create medium");
+        {
+            let mut scheduler = runtime.scheduler.lock();
+            scheduler.schedule(high, HIGH);
+            scheduler.schedule(medium, MEDIUM);
+        }
+
+        let bound = 4 * (LOW_WORK + MEDIUM_WORK);
+        while !high_acquired.load(Ordering::SeqCst) {
+            assert!(runtime.steps() < bound, "high never acquired the lock");
+            runtime.step_for_test();
+        }
+        let steps = runtime.steps();
+        let medium_progress = medium_progress.load(Ordering::SeqCst);
+        runtime.run_until_quiescent();
+        assert!(!mutex.is_locked(), "every guard was released");
+        let boosts = events(&observed)
+            .iter()
+            .filter(|event| event.starts_with("boosted"))
+            .count();
+        InversionRun {
+            steps,
+            medium_progress,
+            boosts,
+        }
+    }
+
+    #[test]
+    fn lab_inheritance_resolves_priority_inversion() {
+        init_test("lab_inheritance_resolves_priority_inversion");
+        let run = run_inversion(true);
+        crate::assert_with_log!(
+            run.medium_progress == 0,
+            "medium work never preempts the boosted holder",
+            0,
+            run.medium_progress
+        );
+        crate::assert_with_log!(
+            run.steps <= LOW_WORK + 8,
+            "high waits only for the holder's critical section",
+            LOW_WORK + 8,
+            run.steps
+        );
+        assert_eq!(run.boosts, 1);
+        assert_eq!(run_inversion(true).steps, run.steps, "boosting is deterministic");
+        crate::test_complete!("lab_inheritance_resolves_priority_inversion");
+    }
+
+    #[test]
+    fn lab_without_inheritance_medium_work_starves_high_waiter() {
+        init_test("lab_without_inheritance_medium_work_starves_high_waiter");
+        let run = run_inversion(false);
+        crate::assert_with_log!(
+            run.medium_progress == MEDIUM_WORK,
+            "medium work runs to completion first",
+            MEDIUM_WORK,
+            run.medium_progress
+        );
+        crate::assert_with_log!(
+            run.steps > MEDIUM_WORK + LOW_WORK,
+            "high waits behind all medium work",
+            MEDIUM_WORK + LOW_WORK,
+            run.steps
+        );
+        assert_eq!(run.boosts, 0);
+        crate::test_complete!("lab_without_inheritance_medium_work_starves_high_waiter");
+    }
+}
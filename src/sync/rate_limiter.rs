@@ -0,0 +1,892 @@
+//! Shared rate limiter with FIFO-fair async permit acquisition.
+//!
+//! [`RateLimiter`] meters work across any number of tasks: clones share one
+//! limit, so call sites that are not a single stream (for example outbound
+//! requests to a partner API issued from many handlers) draw from the same
+//! budget. Two metering algorithms sit behind the same API:
+//!
+//! - [`RateAlgorithm::TokenBucket`]: a permit level refilled continuously up
+//!   to the burst size.
+//! - [`RateAlgorithm::Gcra`]: the generic cell rate algorithm, which tracks a
+//!   single theoretical arrival time instead of a level.
+//!
+//! For a fixed [`RateLimit`] both admit exactly the same schedule; they differ
+//! only in the state they keep.
+//!
+//! # Clock
+//!
+//! Every decision reads the runtime clock through `cx.now()`, so under the
+//! lab runtime's virtual time grant instants are exact and reproducible.
+//!
+//! # Fairness
+//!
+//! Waiters are served strictly FIFO. Only the head of the queue may take
+//! permits, so a large acquire cannot be starved by a stream of small ones,
+//! and [`RateLimiter::try_acquire`] never barges past queued waiters.
+//!
+//! # Cancel Safety
+//!
+//! Permits are taken atomically at the moment of grant. A waiter that is
+//! cancelled, reaches its deadline, or is dropped leaves the queue without
+//! consuming anything and hands the head position to the next waiter.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::sync::{RateLimit, RateLimiter};
+//!
+//! // 500 calls per second, at most 50 back to back.
+//! let limiter = RateLimiter::token_bucket(RateLimit::per_second(500).with_burst(50));
+//!
+//! // Clones share the same budget.
+//! let shared = limiter.clone();
+//! shared.acquire(&cx, 1).await?;
+//! ```
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use crate::cx::Cx;
+use crate::cx::cap::HasTime;
+use crate::time::Sleep;
+use crate::types::Time;
+
+/// Error returned when rate-limited acquisition fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateAcquireError {
+    /// The request is larger than the configured burst and can never be
+    /// granted.
+    ExceedsBurst {
+        /// Permits requested.
+        requested: u32,
+        /// Burst size at the time of the request.
+        burst: u32,
+    },
+    /// Cancelled while waiting.
+    Cancelled,
+    /// The ambient deadline passed before the permits became available.
+    DeadlineExceeded,
+    /// The acquire future was polled after it had already completed.
+    PolledAfterCompletion,
+}
+
+impl fmt::Display for RateAcquireError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExceedsBurst { requested, burst } => {
+                write!(f, "rate limiter request of {requested} permits exceeds burst of {burst}")
+            }
+            Self::Cancelled => write!(f, "rate limiter acquire cancelled"),
+            Self::DeadlineExceeded => write!(f, "rate limiter acquire deadline exceeded"),
+            Self::PolledAfterCompletion => {
+                write!(f, "rate limiter acquire future polled after completion")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RateAcquireError {}
+
+/// Metering algorithm used by a [`RateLimiter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateAlgorithm {
+    /// Token bucket: a permit level refilled continuously up to the burst.
+    TokenBucket,
+    /// Generic cell rate algorithm: a single theoretical arrival time.
+    Gcra,
+}
+
+/// Rate and burst configuration for a [`RateLimiter`].
+///
+/// Zero values are raised to the smallest meaningful limit: one permit, one
+/// nanosecond, a burst of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    permits: u32,
+    period: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// `permits` per `period`, with a burst equal to `permits`.
+    #[must_use]
+    pub fn new(permits: u32, period: Duration) -> Self {
+        let permits = permits.max(1);
+        Self {
+            permits,
+            period: period.max(Duration::from_nanos(1)),
+            burst: permits,
+        }
+    }
+
+    /// `permits` per second, with a burst equal to `permits`.
+    #[must_use]
+    pub fn per_second(permits: u32) -> Self {
+        Self::new(permits, Duration::from_secs(1))
+    }
+
+    /// Sets the burst: the most permits available at once, and therefore the
+    /// largest single acquire that can ever succeed.
+    #[must_use]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Permits replenished per period.
+    #[must_use]
+    pub const fn permits(&self) -> u32 {
+        self.permits
+    }
+
+    /// Replenishment period.
+    #[must_use]
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Burst size.
+    #[must_use]
+    pub const fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    // Metering works in exact integer units: one permit costs `unit()` units
+    // and capacity accrues at `rate()` units per nanosecond.
+
+    fn unit(&self) -> u128 {
+        self.period.as_nanos().min(u128::from(u64::MAX))
+    }
+
+    fn rate(&self) -> u128 {
+        u128::from(self.permits)
+    }
+
+    fn capacity(&self) -> u128 {
+        u128::from(self.burst) * self.unit()
+    }
+
+    fn units_for(&self, permits: u32) -> u128 {
+        u128::from(permits) * self.unit()
+    }
+}
+
+/// Metering state, in the units described on [`RateLimit`]'s helpers.
+#[derive(Debug, Clone, Copy)]
+enum Meter {
+    TokenBucket {
+        level: u128,
+        refilled_at: u64,
+    },
+    /// `tat` is the theoretical arrival time, in nanoseconds scaled by the rate.
+    Gcra {
+        tat: u128,
+    },
+}
+
+impl Meter {
+    /// A meter holding a full burst. Because the level saturates at capacity,
+    /// anchoring it at time zero is equivalent to anchoring it at any later
+    /// first use.
+    fn full(algorithm: RateAlgorithm, limit: &RateLimit) -> Self {
+        match algorithm {
+            RateAlgorithm::TokenBucket => Self::TokenBucket {
+                level: limit.capacity(),
+                refilled_at: 0,
+            },
+            RateAlgorithm::Gcra => Self::Gcra { tat: 0 },
+        }
+    }
+
+    fn level(&self, now: u64, limit: &RateLimit) -> u128 {
+        match *self {
+            Self::TokenBucket { level, refilled_at } => {
+                let elapsed = u128::from(now.saturating_sub(refilled_at));
+                level
+                    .saturating_add(elapsed * limit.rate())
+                    .min(limit.capacity())
+            }
+            Self::Gcra { tat } => {
+                let t = u128::from(now) * limit.rate();
+                limit.capacity().saturating_sub(tat.saturating_sub(t))
+            }
+        }
+    }
+
+    /// Earliest time at which `units` are available, assuming no other taker.
+    fn ready_at(&self, now: u64, limit: &RateLimit, units: u128) -> u64 {
+        let level = self.level(now, limit);
+        if level >= units {
+            return now;
+        }
+        let wait = (units - level).div_ceil(limit.rate());
+        now.saturating_add(u64::try_from(wait).unwrap_or(u64::MAX))
+    }
+
+    fn take(&mut self, now: u64, limit: &RateLimit, units: u128) {
+        let level = self.level(now, limit);
+        debug_assert!(level >= units, "rate limiter took more than its level");
+        match self {
+            Self::TokenBucket { .. } => {
+                *self = Self::TokenBucket {
+                    level: level - units,
+                    refilled_at: now,
+                };
+            }
+            Self::Gcra { tat } => {
+                let t = u128::from(now) * limit.rate();
+                *tat = (*tat).max(t) + units;
+            }
+        }
+    }
+
+    /// Carries the current permit count over to `new`, rescaled to its unit
+    /// and clamped to its burst.
+    fn reconfigure(&mut self, now: u64, old: &RateLimit, new: &RateLimit) {
+        let level = self.level(now, old);
+        let carried = level
+            .checked_mul(new.unit())
+            .map_or(new.capacity(), |scaled| scaled / old.unit())
+            .min(new.capacity());
+        *self = match self {
+            Self::TokenBucket { .. } => Self::TokenBucket {
+                level: carried,
+                refilled_at: now,
+            },
+            Self::Gcra { .. } => Self::Gcra {
+                tat: u128::from(now) * new.rate() + (new.capacity() - carried),
+            },
+        };
+    }
+}
+
+struct Waiter {
+    id: u64,
+    waker: Waker,
+}
+
+struct RateLimiterState {
+    limit: RateLimit,
+    meter: Meter,
+    waiters: VecDeque<Waiter>,
+    next_waiter_id: u64,
+    generation: u64,
+    cancellation_count: u64,
+}
+
+impl RateLimiterState {
+    fn head_waker(&self) -> Option<Waker> {
+        self.waiters.front().map(|waiter| waiter.waker.clone())
+    }
+
+    fn is_head(&self, waiter: Option<u64>) -> bool {
+        waiter.map_or(self.waiters.is_empty(), |id| {
+            self.waiters.front().is_some_and(|head| head.id == id)
+        })
+    }
+
+    fn enqueue(&mut self, waker: &Waker) -> u64 {
+        let id = self.next_waiter_id;
+        self.next_waiter_id = self.next_waiter_id.wrapping_add(1);
+        self.waiters.push_back(Waiter {
+            id,
+            waker: waker.clone(),
+        });
+        id
+    }
+
+    fn refresh_waker(&mut self, id: u64, waker: &Waker) {
+        if let Some(waiter) = self.waiters.iter_mut().find(|waiter| waiter.id == id)
+            && !waiter.waker.will_wake(waker)
+        {
+            waiter.waker = waker.clone();
+        }
+    }
+
+    /// Removes waiter `id`. When it was the head, returns the new head's
+    /// waker so it can re-evaluate outside the lock.
+    fn remove(&mut self, id: u64) -> Option<Waker> {
+        let index = self.waiters.iter().position(|waiter| waiter.id == id)?;
+        self.waiters.remove(index);
+        if index == 0 { self.head_waker() } else { None }
+    }
+}
+
+struct RateLimiterInner {
+    algorithm: RateAlgorithm,
+    state: Mutex<RateLimiterState>,
+}
+
+/// A rate limiter shared across tasks.
+///
+/// Cloning is cheap and every clone meters against the same budget.
+/// Operations that change state take a [`Cx`] and read its clock; read-only
+/// observers take the time explicitly so metrics exporters can call them
+/// without a context.
+#[derive(Clone)]
+pub struct RateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.state.lock();
+        f.debug_struct("RateLimiter")
+            .field("algorithm", &self.inner.algorithm)
+            .field("limit", &state.limit)
+            .field("queue_depth", &state.waiters.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter using `algorithm`, starting with a full burst.
+    #[must_use]
+    pub fn new(algorithm: RateAlgorithm, limit: RateLimit) -> Self {
+        Self {
+            inner: Arc::new(RateLimiterInner {
+                algorithm,
+                state: Mutex::new(RateLimiterState {
+                    limit,
+                    meter: Meter::full(algorithm, &limit),
+                    waiters: VecDeque::new(),
+                    next_waiter_id: 0,
+                    generation: 0,
+                    cancellation_count: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Creates a token-bucket limiter.
+    #[must_use]
+    pub fn token_bucket(limit: RateLimit) -> Self {
+        Self::new(RateAlgorithm::TokenBucket, limit)
+    }
+
+    /// Creates a GCRA limiter.
+    #[must_use]
+    pub fn gcra(limit: RateLimit) -> Self {
+        Self::new(RateAlgorithm::Gcra, limit)
+    }
+
+    /// Returns the metering algorithm.
+    #[must_use]
+    pub fn algorithm(&self) -> RateAlgorithm {
+        self.inner.algorithm
+    }
+
+    /// Returns the current limit.
+    #[must_use]
+    pub fn limit(&self) -> RateLimit {
+        self.inner.state.lock().limit
+    }
+
+    /// Returns the number of queued waiters.
+    #[must_use]
+    pub fn queue_depth(&self) -> usize {
+        self.inner.state.lock().waiters.len()
+    }
+
+    /// Returns the whole permits available at `now`, ignoring queued waiters.
+    #[must_use]
+    pub fn available_permits(&self, now: Time) -> u32 {
+        let state = self.inner.state.lock();
+        let level = state.meter.level(now.as_nanos(), &state.limit);
+        u32::try_from(level / state.limit.unit()).unwrap_or(u32::MAX)
+    }
+
+    /// Takes `permits` immediately if they are available and no waiter is
+    /// queued ahead. Returns `true` when the permits were taken.
+    pub fn try_acquire<Caps: HasTime>(&self, cx: &Cx<Caps>, permits: u32) -> bool {
+        if permits == 0 {
+            return true;
+        }
+        let now = cx.now().as_nanos();
+        let mut guard = self.inner.state.lock();
+        let state = &mut *guard;
+        if permits > state.limit.burst || !state.waiters.is_empty() {
+            return false;
+        }
+        let units = state.limit.units_for(permits);
+        if state.meter.level(now, &state.limit) < units {
+            return false;
+        }
+        state.meter.take(now, &state.limit, units);
+        true
+    }
+
+    /// Waits until `permits` are available, then takes them.
+    ///
+    /// Waiters are served FIFO. The wait ends early with
+    /// [`RateAcquireError::DeadlineExceeded`] when the ambient deadline
+    /// passes and with [`RateAcquireError::Cancelled`] on cancellation;
+    /// neither consumes permits. Requests larger than the burst fail with
+    /// [`RateAcquireError::ExceedsBurst`].
+    pub fn acquire<'a, Caps>(
+        &'a self,
+        cx: &'a Cx<Caps>,
+        permits: u32,
+    ) -> RateAcquireFuture<'a, Caps> {
+        RateAcquireFuture {
+            limiter: self,
+            cx,
+            permits,
+            waiter: None,
+            sleep: None,
+            completed: false,
+        }
+    }
+
+    /// Replaces the limit without dropping queued waiters.
+    ///
+    /// The permits available now carry over, clamped to the new burst; the
+    /// head waiter is woken to re-evaluate against the new rate. A queued
+    /// request larger than the new burst fails with
+    /// [`RateAcquireError::ExceedsBurst`] when it reaches the head.
+    pub fn reconfigure<Caps: HasTime>(&self, cx: &Cx<Caps>, limit: RateLimit) {
+        let now = cx.now().as_nanos();
+        let head = {
+            let mut guard = self.inner.state.lock();
+            let state = &mut *guard;
+            state.meter.reconfigure(now, &state.limit, &limit);
+            state.limit = limit;
+            state.generation = state.generation.wrapping_add(1);
+            state.head_waker()
+        };
+        if let Some(head) = head {
+            head.wake();
+        }
+    }
+
+    /// Returns a redacted telemetry snapshot evaluated at `now`.
+    ///
+    /// `generation` counts reconfigurations.
+    #[must_use]
+    pub fn telemetry_snapshot(
+        &self,
+        primitive_id: u64,
+        now: Time,
+    ) -> crate::sync::SyncTelemetrySnapshot {
+        let state = self.inner.state.lock();
+        let level = state.meter.level(now.as_nanos(), &state.limit);
+        let available = usize::try_from(level / state.limit.unit()).unwrap_or(usize::MAX);
+        let capacity = state.limit.burst as usize;
+        let state_label = if !state.waiters.is_empty() {
+            "waiting"
+        } else if available == 0 {
+            "saturated"
+        } else {
+            "open"
+        };
+        crate::sync::SyncTelemetrySnapshot {
+            primitive_id,
+            primitive_kind: "rate_limiter",
+            capacity,
+            occupied_units: capacity.saturating_sub(available),
+            available_units: available,
+            waiter_count: state.waiters.len(),
+            generation: state.generation,
+            state: state_label,
+            cancellation_count: state.cancellation_count,
+            closed: false,
+        }
+    }
+}
+
+/// Future returned by `RateLimiter::acquire`.
+pub struct RateAcquireFuture<'a, Caps = crate::cx::cap::All> {
+    limiter: &'a RateLimiter,
+    cx: &'a Cx<Caps>,
+    permits: u32,
+    waiter: Option<u64>,
+    sleep: Option<Sleep>,
+    completed: bool,
+}
+
+impl<Caps> RateAcquireFuture<'_, Caps> {
+    /// Leaves the queue without taking permits and completes with `error`.
+    fn abandon(&mut self, error: RateAcquireError) -> Poll<Result<(), RateAcquireError>> {
+        let waiter = self.waiter.take();
+        let next = {
+            let mut state = self.limiter.inner.state.lock();
+            if matches!(error, RateAcquireError::Cancelled | RateAcquireError::DeadlineExceeded) {
+                state.cancellation_count = state.cancellation_count.saturating_add(1);
+            }
+            waiter.and_then(|id| state.remove(id))
+        };
+        self.completed = true;
+        self.sleep = None;
+        if let Some(next) = next {
+            next.wake();
+        }
+        Poll::Ready(Err(error))
+    }
+}
+
+impl<Caps> Drop for RateAcquireFuture<'_, Caps> {
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter.take() {
+            let next = {
+                let mut state = self.limiter.inner.state.lock();
+                state.cancellation_count = state.cancellation_count.saturating_add(1);
+                state.remove(id)
+            };
+            if let Some(next) = next {
+                next.wake();
+            }
+        }
+    }
+}
+
+impl<Caps: HasTime> Future for RateAcquireFuture<'_, Caps> {
+    type Output = Result<(), RateAcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if this.completed {
+            return Poll::Ready(Err(RateAcquireError::PolledAfterCompletion));
+        }
+        if this.permits == 0 {
+            this.completed = true;
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            let now = this.cx.now();
+            let deadline = this.cx.deadline();
+            // An exhausted budget also fails the checkpoint; check the
+            // deadline first so callers can tell the two apart.
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                return this.abandon(RateAcquireError::DeadlineExceeded);
+            }
+            if this.cx.checkpoint().is_err() {
+                return this.abandon(RateAcquireError::Cancelled);
+            }
+
+            let now_ns = now.as_nanos();
+            let mut guard = this.limiter.inner.state.lock();
+            let state = &mut *guard;
+            let burst = state.limit.burst;
+            if this.permits > burst {
+                drop(guard);
+                return this.abandon(RateAcquireError::ExceedsBurst {
+                    requested: this.permits,
+                    burst,
+                });
+            }
+
+            // FIFO fairness: only the head of the queue may take permits.
+            let wake_at = if state.is_head(this.waiter) {
+                let units = state.limit.units_for(this.permits);
+                let ready_at = state.meter.ready_at(now_ns, &state.limit, units);
+                if ready_at <= now_ns {
+                    state.meter.take(now_ns, &state.limit, units);
+                    let next = this.waiter.take().and_then(|id| state.remove(id));
+                    drop(guard);
+                    this.completed = true;
+                    this.sleep = None;
+                    if let Some(next) = next {
+                        next.wake();
+                    }
+                    return Poll::Ready(Ok(()));
+                }
+                Some(Time::from_nanos(ready_at))
+            } else {
+                None
+            };
+            match this.waiter {
+                Some(id) => state.refresh_waker(id, context.waker()),
+                None => this.waiter = Some(state.enqueue(context.waker())),
+            }
+            drop(guard);
+
+            // The head sleeps until its permits accrue; everyone else waits to
+            // be woken by the waiter ahead. Both are bounded by the deadline.
+            let target = match (wake_at, deadline) {
+                (Some(ready), Some(deadline)) => Some(ready.min(deadline)),
+                (ready, deadline) => ready.or(deadline),
+            };
+            let Some(target) = target else {
+                this.sleep = None;
+                return Poll::Pending;
+            };
+            if this
+                .sleep
+                .as_ref()
+                .is_none_or(|sleep| sleep.deadline() != target)
+            {
+                this.sleep = Some(Sleep::new(target));
+            }
+            let sleep = this.sleep.as_mut().expect("sleep armed above");
+            if Pin::new(sleep).poll(context).is_pending() {
+                return Poll::Pending;
+            }
+            // The timer fired: re-evaluate at the new time with a fresh timer.
+            this.sleep = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lab::{AutoAdvanceTermination, LabRuntime};
+    use crate::test_utils::spawn_at;
+    use crate::time::{sleep_until, timeout};
+    use crate::types::{Budget, RegionId};
+
+    type Log = Arc<Mutex<Vec<(usize, Result<(), RateAcquireError>, u64)>>>;
+
+    const ALGORITHMS: [RateAlgorithm; 2] = [RateAlgorithm::TokenBucket, RateAlgorithm::Gcra];
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Spawns a task that acquires `permits` at `at_ms`, logging
+    /// `(label, outcome, completion ms)`.
+    fn spawn_acquire(
+        runtime: &mut LabRuntime,
+        region: RegionId,
+        limiter: &RateLimiter,
+        log: &Log,
+        (label, at_ms, permits): (usize, u64, u32),
+    ) {
+        let limiter = limiter.clone();
+        let log = Arc::clone(log);
+        spawn_at(runtime, region, Budget::INFINITE, at_ms, move |cx| async move {
+            let outcome = limiter.acquire(&cx, permits).await;
+            log.lock().push((label, outcome, cx.now().as_millis()));
+        });
+    }
+
+    fn run(runtime: &mut LabRuntime) {
+        let report = runtime.run_with_auto_advance();
+        assert_eq!(report.termination, AutoAdvanceTermination::Quiescent);
+    }
+
+    fn grants(log: &Log) -> Vec<(usize, u64)> {
+        let mut grants: Vec<_> = log
+            .lock()
+            .iter()
+            .filter(|(_, outcome, _)| outcome.is_ok())
+            .map(|&(label, _, at)| (label, at))
+            .collect();
+        grants.sort_unstable();
+        grants
+    }
+
+    #[test]
+    fn scripted_arrivals_emit_exact_schedule() {
+        init_test("scripted_arrivals_emit_exact_schedule");
+        for algorithm in ALGORITHMS {
+            // 10/s is one permit per 100ms; the first three ride the burst.
+            let limiter = RateLimiter::new(algorithm, RateLimit::per_second(10).with_burst(3));
+            let log = Log::default();
+            let mut runtime = LabRuntime::with_seed(1);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            for (label, at_ms) in [0, 1, 2, 3, 4, 250, 1000].into_iter().enumerate() {
+                spawn_acquire(&mut runtime, root, &limiter, &log, (label, at_ms, 1));
+            }
+            run(&mut runtime);
+
+            let expected = vec![(0, 0), (1, 1), (2, 2), (3, 100), (4, 200), (5, 300), (6, 1000)];
+            crate::assert_with_log!(
+                grants(&log) == expected,
+                "emission schedule",
+                expected,
+                grants(&log)
+            );
+            assert_eq!(limiter.queue_depth(), 0);
+        }
+        crate::test_complete!("scripted_arrivals_emit_exact_schedule");
+    }
+
+    #[test]
+    fn burst_caps_accumulation_and_request_size() {
+        init_test("burst_caps_accumulation_and_request_size");
+        for algorithm in ALGORITHMS {
+            let limiter = RateLimiter::new(algorithm, RateLimit::per_second(1).with_burst(5));
+            let observed = Arc::new(Mutex::new(Vec::new()));
+            let mut runtime = LabRuntime::with_seed(2);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            let task_limiter = limiter.clone();
+            let task_observed = Arc::clone(&observed);
+            spawn_at(&mut runtime, root, Budget::INFINITE, 0, move |cx| async move {
+                let mut observed = Vec::new();
+                observed.push(u32::from(task_limiter.try_acquire(&cx, 5)));
+                observed.push(u32::from(task_limiter.try_acquire(&cx, 1)));
+                let oversized = task_limiter.acquire(&cx, 6).await;
+                assert_eq!(
+                    oversized,
+                    Err(RateAcquireError::ExceedsBurst {
+                        requested: 6,
+                        burst: 5,
+                    })
+                );
+                sleep_until(Time::from_secs(2)).await;
+                observed.push(task_limiter.available_permits(cx.now()));
+                // Idle time beyond the burst is not banked.
+                sleep_until(Time::from_secs(60)).await;
+                observed.push(task_limiter.available_permits(cx.now()));
+                task_limiter.acquire(&cx, 5).await.expect("full burst");
+                observed.push(u32::try_from(cx.now().as_secs()).expect("secs fit"));
+                *task_observed.lock() = observed;
+            });
+            run(&mut runtime);
+
+            let observed = observed.lock().clone();
+            crate::assert_with_log!(
+                observed == vec![1, 0, 2, 5, 60],
+                "burst observations",
+                vec![1, 0, 2, 5, 60],
+                observed
+            );
+            let snapshot = limiter.telemetry_snapshot(7, Time::from_secs(60));
+            assert_eq!(snapshot.capacity, 5);
+            assert_eq!(snapshot.available_units, 0);
+            assert_eq!(snapshot.state, "saturated");
+            assert_eq!(snapshot.cancellation_count, 0);
+        }
+        crate::test_complete!("burst_caps_accumulation_and_request_size");
+    }
+
+    #[test]
+    fn reconfiguration_keeps_queued_waiters() {
+        init_test("reconfiguration_keeps_queued_waiters");
+        for algorithm in ALGORITHMS {
+            let limiter = RateLimiter::new(algorithm, RateLimit::per_second(10).with_burst(2));
+            let log = Log::default();
+            let mut runtime = LabRuntime::with_seed(3);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            spawn_acquire(&mut runtime, root, &limiter, &log, (0, 0, 2));
+            spawn_acquire(&mut runtime, root, &limiter, &log, (1, 1, 1));
+            spawn_acquire(&mut runtime, root, &limiter, &log, (2, 2, 1));
+
+            // At 50ms half a permit has accrued and both waiters are queued.
+            // The half permit carries over to the faster rate.
+            let depth = Arc::new(Mutex::new(None));
+            let controller = limiter.clone();
+            let controller_depth = Arc::clone(&depth);
+            spawn_at(&mut runtime, root, Budget::INFINITE, 50, move |cx| async move {
+                *controller_depth.lock() = Some(controller.queue_depth());
+                controller.reconfigure(&cx, RateLimit::per_second(100).with_burst(2));
+            });
+            run(&mut runtime);
+
+            assert_eq!(*depth.lock(), Some(2));
+            let expected = vec![(0, 0), (1, 55), (2, 65)];
+            crate::assert_with_log!(
+                grants(&log) == expected,
+                "schedule across reconfiguration",
+                expected,
+                grants(&log)
+            );
+            assert_eq!(limiter.limit(), RateLimit::per_second(100).with_burst(2));
+            assert_eq!(limiter.telemetry_snapshot(1, Time::from_millis(65)).generation, 1);
+        }
+        crate::test_complete!("reconfiguration_keeps_queued_waiters");
+    }
+
+    #[test]
+    fn cancelled_waiters_do_not_consume_permits() {
+        init_test("cancelled_waiters_do_not_consume_permits");
+        for algorithm in ALGORITHMS {
+            let limiter = RateLimiter::new(algorithm, RateLimit::per_second(10).with_burst(1));
+            let log = Log::default();
+            let mut runtime = LabRuntime::with_seed(4);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            spawn_acquire(&mut runtime, root, &limiter, &log, (0, 0, 1));
+
+            // Head waiter whose acquire future is dropped by a timeout at 21ms.
+            let dropped = limiter.clone();
+            let dropped_at = Arc::new(Mutex::new(None));
+            let dropped_slot = Arc::clone(&dropped_at);
+            spawn_at(&mut runtime, root, Budget::INFINITE, 1, move |cx| async move {
+                let outcome =
+                    timeout(cx.now(), Duration::from_millis(20), dropped.acquire(&cx, 1)).await;
+                if outcome.is_err() {
+                    *dropped_slot.lock() = Some(cx.now().as_millis());
+                }
+            });
+
+            // Waiter whose ambient deadline passes at 50ms.
+            let expiring = limiter.clone();
+            let expiring_log = Arc::clone(&log);
+            let budget = Budget::INFINITE.with_deadline(Time::from_millis(50));
+            spawn_at(&mut runtime, root, budget, 2, move |cx| async move {
+                let outcome = expiring.acquire(&cx, 1).await;
+                expiring_log.lock().push((2, outcome, cx.now().as_millis()));
+            });
+
+            spawn_acquire(&mut runtime, root, &limiter, &log, (3, 3, 1));
+            spawn_acquire(&mut runtime, root, &limiter, &log, (4, 4, 1));
+            run(&mut runtime);
+
+            assert_eq!(*dropped_at.lock(), Some(21));
+            let mut outcomes = log.lock().clone();
+            outcomes.sort_unstable_by_key(|&(label, _, _)| label);
+            let expected = vec![
+                (0, Ok(()), 0),
+                (2, Err(RateAcquireError::DeadlineExceeded), 50),
+                // Neither abandoned waiter took the permit accruing at 100ms.
+                (3, Ok(()), 100),
+                (4, Ok(()), 200),
+            ];
+            crate::assert_with_log!(outcomes == expected, "outcomes", expected, outcomes);
+            let snapshot = limiter.telemetry_snapshot(1, Time::from_millis(200));
+            assert_eq!(snapshot.waiter_count, 0);
+            assert_eq!(snapshot.cancellation_count, 2);
+        }
+        crate::test_complete!("cancelled_waiters_do_not_consume_permits");
+    }
+
+    #[test]
+    fn fifo_serves_large_acquire_before_later_small_ones() {
+        init_test("fifo_serves_large_acquire_before_later_small_ones");
+        for algorithm in ALGORITHMS {
+            let limiter = RateLimiter::new(algorithm, RateLimit::per_second(10).with_burst(5));
+            let log = Log::default();
+            let mut runtime = LabRuntime::with_seed(5);
+            let root = runtime.state.create_root_region(Budget::INFINITE);
+            spawn_acquire(&mut runtime, root, &limiter, &log, (0, 0, 5));
+            spawn_acquire(&mut runtime, root, &limiter, &log, (1, 1, 4));
+            for (label, at_ms) in [(2, 2), (3, 3), (4, 4)] {
+                spawn_acquire(&mut runtime, root, &limiter, &log, (label, at_ms, 1));
+            }
+
+            // At 150ms one and a half permits have accrued, enough for a small
+            // request, but the large head waiter must not be bypassed.
+            let probe = Arc::new(Mutex::new(None));
+            let prober = limiter.clone();
+            let probe_slot = Arc::clone(&probe);
+            spawn_at(&mut runtime, root, Budget::INFINITE, 150, move |cx| async move {
+                *probe_slot.lock() = Some((
+                    prober.try_acquire(&cx, 1),
+                    prober.queue_depth(),
+                    prober.available_permits(cx.now()),
+                ));
+            });
+            run(&mut runtime);
+
+            assert_eq!(*probe.lock(), Some((false, 4, 1)));
+            let expected = vec![(0, 0), (1, 400), (2, 500), (3, 600), (4, 700)];
+            crate::assert_with_log!(
+                grants(&log) == expected,
+                "FIFO schedule",
+                expected,
+                grants(&log)
+            );
+        }
+        crate::test_complete!("fifo_serves_large_acquire_before_later_small_ones");
+    }
+}
@@ -32,6 +32,12 @@
 //!   at most N writer cycles without letting an older writer sit behind an
 //!   unbounded tail of younger readers (br-asupersync-4j40bb).
 //!
+//! ## Priority Inheritance
+//!
+//! A lock built with [`RwLock::with_priority_inheritance`] lends the priority
+//! of its highest-priority queued writer to the active writer until the write
+//! guard is released or downgraded. Readers neither donate nor inherit.
+//!
 //! ## When to Use RwLock vs Mutex
 //!
 //! Prefer **RwLock** when:
@@ -68,14 +74,17 @@ use smallvec::SmallVec;
 use std::cell::UnsafeCell;
 use std::future::Future;
 use std::ops::{Deref, DerefMut};
+use std::panic::Location;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::{Context, Poll, Waker};
 
+use super::poison::LockPoisonEvent;
 use super::waiter::{WaiterChain, WaiterId};
 use crate::cx::Cx;
 use crate::sync::lock_ordering::{self, LockRank};
+use crate::sync::priority_inheritance::{InheritanceWait, PriorityInheritance};
 
 /// br-asupersync-4j40bb: bound on consecutive writers served from the queue
 /// while readers are also queued. After this many writer hand-offs in a row,
@@ -192,17 +201,23 @@ struct State {
 /// # Poisoning
 ///
 /// If a panic occurs while holding a **write** guard, the lock is poisoned.
-/// Subsequent acquisition attempts will return `RwLockError::Poisoned`.
-/// Read guards do not poison the lock since they cannot corrupt data.
+/// Subsequent acquisition attempts will return `RwLockError::Poisoned` until
+/// [`clear_poison`](Self::clear_poison) is called, and a
+/// [`LockPoisonEvent`](crate::sync::LockPoisonEvent) is logged. Read guards
+/// do not poison the lock since they cannot corrupt data.
 #[derive(Debug)]
 pub struct RwLock<T> {
     state: ParkingMutex<State>,
     data: UnsafeCell<T>,
     poisoned: AtomicBool,
+    /// Source location that constructed the lock.
+    site: &'static Location<'static>,
     /// Human-readable name for lock ordering (e.g., "tasks", "regions").
     name: &'static str,
     /// Lock rank for deadlock prevention.
     rank: Option<LockRank>,
+    /// Write-side priority-inheritance bookkeeping, when enabled.
+    inheritance: Option<Arc<PriorityInheritance>>,
 }
 
 unsafe impl<T: Send> Send for RwLock<T> {}
@@ -212,23 +227,60 @@ impl<T> RwLock<T> {
     /// Creates a new lock with the given value and name for lock ordering.
     #[inline]
     #[must_use]
+    #[track_caller]
     pub fn with_name(name: &'static str, value: T) -> Self {
         let rank = lock_ordering::rank_for_lock_name(name);
         Self {
             state: ParkingMutex::new(State::default()),
             data: UnsafeCell::new(value),
             poisoned: AtomicBool::new(false),
+            site: Location::caller(),
             name,
             rank,
+            inheritance: None,
         }
     }
 
+    /// Creates a new named lock whose active writer inherits the priority of
+    /// its highest-priority queued writer until the write guard is released.
+    ///
+    /// Donations are forwarded through at most
+    /// [`DEFAULT_MAX_INHERITANCE_DEPTH`](crate::sync::DEFAULT_MAX_INHERITANCE_DEPTH)
+    /// nested inheriting locks.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn with_priority_inheritance(name: &'static str, value: T) -> Self {
+        Self::with_priority_inheritance_depth(
+            name,
+            value,
+            crate::sync::DEFAULT_MAX_INHERITANCE_DEPTH,
+        )
+    }
+
+    /// Like [`with_priority_inheritance`](Self::with_priority_inheritance),
+    /// forwarding donations through at most `max_depth` nested inheriting
+    /// locks (minimum 1).
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn with_priority_inheritance_depth(
+        name: &'static str,
+        value: T,
+        max_depth: usize,
+    ) -> Self {
+        let mut lock = Self::with_name(name, value);
+        lock.inheritance = Some(Arc::new(PriorityInheritance::new(max_depth)));
+        lock
+    }
+
     /// Creates a new lock containing the given value with default naming.
     ///
     /// Note: For proper deadlock prevention, prefer `with_name()` to specify
     /// the lock's role in the lock hierarchy (e.g., "tasks", "regions").
     #[inline]
     #[must_use]
+    #[track_caller]
     pub fn new(value: T) -> Self {
         Self::with_name("unknown", value)
     }
@@ -255,6 +307,34 @@ impl<T> RwLock<T> {
         self.poisoned.load(Ordering::Acquire)
     }
 
+    /// Clears the poisoned flag once the protected value's invariants have
+    /// been restored.
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release);
+    }
+
+    /// Returns the source location that constructed this lock.
+    #[inline]
+    #[must_use]
+    pub fn construction_site(&self) -> &'static Location<'static> {
+        self.site
+    }
+
+    /// Poisons the lock on behalf of a write guard dropped during a panic.
+    fn poison_on_panic(&self) {
+        if !self.poisoned.swap(true, Ordering::AcqRel) {
+            LockPoisonEvent::capture("rwlock", self.name, self.site).emit();
+        }
+    }
+
+    /// Returns true if the write side applies priority inheritance.
+    #[inline]
+    #[must_use]
+    pub fn has_priority_inheritance(&self) -> bool {
+        self.inheritance.is_some()
+    }
+
     /// Acquires a read guard asynchronously, waiting if necessary.
     ///
     /// This is cancel-safe: cancellation while waiting returns an error
@@ -286,6 +366,7 @@ impl<T> RwLock<T> {
             lock: self,
             cx,
             waiter_id: None,
+            inheritance_wait: None,
             counted: false,
             completed: false,
         }
@@ -365,10 +446,46 @@ impl<T> RwLock<T> {
         if let Some(rank) = self.rank {
             lock_ordering::record_acquire(self.name, rank);
         }
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.acquired_by_current();
+        }
 
         Ok(())
     }
 
+    /// Donates a queued writer's priority to the active writer, once per wait.
+    #[inline]
+    fn inheritance_begin_wait<Caps>(&self, cx: &Cx<Caps>, wait: &mut Option<InheritanceWait>) {
+        if wait.is_none()
+            && let Some(inheritance) = &self.inheritance
+        {
+            *wait = Some(inheritance.begin_wait(cx));
+        }
+    }
+
+    /// Publishes a new writer as the holder of an inheriting lock.
+    #[inline]
+    fn inheritance_acquired<Caps>(
+        &self,
+        cx: &Cx<Caps>,
+        waker: &Waker,
+        wait: &mut Option<InheritanceWait>,
+    ) {
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.end_wait(wait);
+            inheritance.acquired(cx, waker);
+        }
+    }
+
+    /// Withdraws the active writer's inherited priority. Runs before the
+    /// write side is handed on so the next writer's donation survives.
+    #[inline]
+    fn inheritance_released(&self) {
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.released();
+        }
+    }
+
     #[inline]
     fn pop_writer_waiter(state: &mut State) -> Option<Waker> {
         state.writer_queue.pop_front().map(|(_, waker, _)| waker)
@@ -509,6 +626,7 @@ impl<T> RwLock<T> {
 
     #[inline]
     fn release_writer(&self) {
+        self.inheritance_released();
         let (writer_waker, reader_wakers) = {
             let mut state = self.state.lock();
             state.writer_active = false;
@@ -632,7 +750,15 @@ impl<T> RwLock<T> {
     }
 
     #[inline]
-    fn abandon_write_waiter(&self, waiter_id: &mut Option<WaiterId>, counted: &mut bool) {
+    fn abandon_write_waiter(
+        &self,
+        waiter_id: &mut Option<WaiterId>,
+        counted: &mut bool,
+        inheritance_wait: &mut Option<InheritanceWait>,
+    ) {
+        if let Some(inheritance) = &self.inheritance {
+            inheritance.end_wait(inheritance_wait);
+        }
         if !*counted {
             return;
         }
@@ -860,6 +986,8 @@ pub struct WriteFuture<'a, 'b, T, Caps = crate::cx::cap::All> {
     lock: &'a RwLock<T>,
     cx: &'b Cx<Caps>,
     waiter_id: Option<WaiterId>,
+    /// Donation registered with an inheriting lock while queued.
+    inheritance_wait: Option<InheritanceWait>,
     counted: bool,
     completed: bool,
 }
@@ -874,8 +1002,11 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
             return Poll::Ready(Err(RwLockError::PolledAfterCompletion));
         }
         if this.cx.checkpoint().is_err() {
-            this.lock
-                .abandon_write_waiter(&mut this.waiter_id, &mut this.counted);
+            this.lock.abandon_write_waiter(
+                &mut this.waiter_id,
+                &mut this.counted,
+                &mut this.inheritance_wait,
+            );
             this.completed = true;
             return Poll::Ready(Err(RwLockError::Cancelled));
         }
@@ -886,8 +1017,11 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
 
             if this.lock.is_poisoned() {
                 drop(state);
-                this.lock
-                    .abandon_write_waiter(&mut this.waiter_id, &mut this.counted);
+                this.lock.abandon_write_waiter(
+                    &mut this.waiter_id,
+                    &mut this.counted,
+                    &mut this.inheritance_wait,
+                );
                 this.completed = true;
                 return Poll::Ready(Err(RwLockError::Poisoned));
             }
@@ -927,6 +1061,11 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
                             this.counted = false;
                         }
                         drop(state);
+                        this.lock.inheritance_acquired(
+                            this.cx,
+                            context.waker(),
+                            &mut this.inheritance_wait,
+                        );
                         this.completed = true;
                         return Poll::Ready(Ok(RwLockWriteGuard { lock: this.lock }));
                     }
@@ -951,6 +1090,8 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
                     lock_ordering::record_acquire(this.lock.name, rank);
                 }
 
+                this.lock
+                    .inheritance_acquired(this.cx, context.waker(), &mut this.inheritance_wait);
                 this.completed = true;
                 return Poll::Ready(Ok(RwLockWriteGuard { lock: this.lock }));
             }
@@ -974,6 +1115,7 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
             );
             drop(state);
             this.waiter_id = Some(waiter_id);
+            this.lock.inheritance_begin_wait(this.cx, &mut this.inheritance_wait);
             return Poll::Pending;
         }
     }
@@ -981,8 +1123,11 @@ impl<'a, T, Caps> Future for WriteFuture<'a, '_, T, Caps> {
 
 impl<T, Caps> Drop for WriteFuture<'_, '_, T, Caps> {
     fn drop(&mut self) {
-        self.lock
-            .abandon_write_waiter(&mut self.waiter_id, &mut self.counted);
+        self.lock.abandon_write_waiter(
+            &mut self.waiter_id,
+            &mut self.counted,
+            &mut self.inheritance_wait,
+        );
     }
 }
 
@@ -1059,7 +1204,7 @@ impl<T> Drop for RwLockWriteGuard<'_, T> {
     #[inline]
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.lock.poisoned.store(true, Ordering::Release);
+            self.lock.poison_on_panic();
         }
         self.lock.release_writer();
 
@@ -1088,6 +1233,7 @@ impl<'a, T> RwLockWriteGuard<'a, T> {
     /// ```
     pub fn downgrade(self) -> RwLockReadGuard<'a, T> {
         let md = std::mem::ManuallyDrop::new(self);
+        md.lock.inheritance_released();
         let read_guard = RwLockReadGuard { lock: md.lock };
 
         // Atomically transition from writer to reader
@@ -1191,6 +1337,7 @@ impl<T> OwnedRwLockWriteGuard<T> {
             lock,
             cx,
             waiter_id: None,
+            inheritance_wait: None,
             counted: false,
             completed: false,
         }
@@ -1238,7 +1385,7 @@ impl<T> Drop for OwnedRwLockWriteGuard<T> {
     #[inline]
     fn drop(&mut self) {
         if std::thread::panicking() {
-            self.lock.poisoned.store(true, Ordering::Release);
+            self.lock.poison_on_panic();
         }
         self.lock.release_writer();
 
@@ -1270,6 +1417,7 @@ impl<T> OwnedRwLockWriteGuard<T> {
         let lock = unsafe { std::ptr::read(&md.lock) };
 
         let read_guard = OwnedRwLockReadGuard { lock };
+        read_guard.lock.inheritance_released();
 
         // Atomically transition from writer to reader
         let reader_wakers = {
@@ -1425,6 +1573,8 @@ pub struct OwnedWriteFuture<'b, T, Caps = crate::cx::cap::All> {
     lock: Arc<RwLock<T>>,
     cx: &'b Cx<Caps>,
     waiter_id: Option<WaiterId>,
+    /// Donation registered with an inheriting lock while queued.
+    inheritance_wait: Option<InheritanceWait>,
     counted: bool,
     completed: bool,
 }
@@ -1440,8 +1590,11 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
         }
 
         if this.cx.checkpoint().is_err() {
-            this.lock
-                .abandon_write_waiter(&mut this.waiter_id, &mut this.counted);
+            this.lock.abandon_write_waiter(
+                &mut this.waiter_id,
+                &mut this.counted,
+                &mut this.inheritance_wait,
+            );
             this.completed = true;
             return Poll::Ready(Err(RwLockError::Cancelled));
         }
@@ -1452,8 +1605,11 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
 
             if this.lock.is_poisoned() {
                 drop(state);
-                this.lock
-                    .abandon_write_waiter(&mut this.waiter_id, &mut this.counted);
+                this.lock.abandon_write_waiter(
+                    &mut this.waiter_id,
+                    &mut this.counted,
+                    &mut this.inheritance_wait,
+                );
                 this.completed = true;
                 return Poll::Ready(Err(RwLockError::Poisoned));
             }
@@ -1495,6 +1651,11 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
                             this.counted = false;
                         }
                         drop(state);
+                        this.lock.inheritance_acquired(
+                            this.cx,
+                            context.waker(),
+                            &mut this.inheritance_wait,
+                        );
                         this.completed = true;
                         return Poll::Ready(Ok(OwnedRwLockWriteGuard {
                             lock: Arc::clone(&this.lock),
@@ -1520,6 +1681,8 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
                 if let Some(rank) = this.lock.rank {
                     lock_ordering::record_acquire(this.lock.name, rank);
                 }
+                this.lock
+                    .inheritance_acquired(this.cx, context.waker(), &mut this.inheritance_wait);
 
                 this.completed = true;
                 return Poll::Ready(Ok(OwnedRwLockWriteGuard {
@@ -1549,6 +1712,7 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
             );
             drop(state);
             this.waiter_id = Some(waiter_id);
+            this.lock.inheritance_begin_wait(this.cx, &mut this.inheritance_wait);
             return Poll::Pending;
         }
     }
@@ -1556,8 +1720,11 @@ impl<T, Caps> Future for OwnedWriteFuture<'_, T, Caps> {
 
 impl<T, Caps> Drop for OwnedWriteFuture<'_, T, Caps> {
     fn drop(&mut self) {
-        self.lock
-            .abandon_write_waiter(&mut self.waiter_id, &mut self.counted);
+        self.lock.abandon_write_waiter(
+            &mut self.waiter_id,
+            &mut self.counted,
+            &mut self.inheritance_wait,
+        );
     }
 }
 
@@ -1567,6 +1734,7 @@ impl<T, Caps> Drop for OwnedWriteFuture<'_, T, Caps> {
 mod tests {
     use super::*;
     use crate::cx::cap;
+    use crate::observability::{LogCollector, LogLevel};
     use crate::test_utils::init_test_logging;
     use crate::util::ArenaIndex;
     use std::sync::Arc as StdArc;
@@ -3395,6 +3563,60 @@ mod tests {
         crate::test_complete!("rwlock_poison_propagation");
     }
 
+    #[test]
+    fn rwlock_read_panic_does_not_poison_but_write_panic_logs_and_clears() {
+        init_test("rwlock_read_panic_does_not_poison_but_write_panic_logs_and_clears");
+        let collector = LogCollector::new(16).with_min_level(LogLevel::Debug);
+        let observer = test_cx();
+        observer.set_log_collector(collector.clone());
+        let _current = Cx::set_current(Some(observer));
+        let lock = RwLock::with_name("routes", vec![1_u32]);
+        let events = || {
+            collector
+                .peek()
+                .into_iter()
+                .filter(|entry| entry.message() == LockPoisonEvent::MESSAGE)
+                .collect::<Vec<_>>()
+        };
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _routes = lock.try_read().expect("unlocked");
+            panic!("reader failed");
+        }));
+        assert!(panicked.is_err());
+        crate::assert_with_log!(
+            !lock.is_poisoned(),
+            "read-holder panic leaves the lock healthy",
+            false,
+            lock.is_poisoned()
+        );
+        assert!(events().is_empty());
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut routes = lock.try_write().expect("unlocked");
+            routes.push(2);
+            panic!("writer failed");
+        }));
+        assert!(panicked.is_err());
+        crate::assert_with_log!(
+            lock.is_poisoned(),
+            "write-holder panic poisons",
+            true,
+            lock.is_poisoned()
+        );
+        let logged = events();
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert_eq!(logged[0].get_field("primitive"), Some("rwlock"));
+        assert_eq!(logged[0].get_field("lock"), Some("routes"));
+        let site = lock.construction_site().to_string();
+        assert_eq!(logged[0].get_field("site"), Some(site.as_str()));
+
+        lock.clear_poison();
+        let routes = lock.try_read().expect("clear_poison re-admits readers");
+        assert_eq!(*routes, vec![1, 2]);
+        crate::test_complete!("rwlock_read_panic_does_not_poison_but_write_panic_logs_and_clears");
+    }
+
     // Pure data-type tests (wave 38 – CyanBarn)
 
     #[test]
@@ -24,18 +24,18 @@
 //! drop(permit);
 //! ```
 
-use parking_lot::Mutex as ParkingMutex;
 use slab::Slab;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::task::{Context, Poll, Waker};
 
 use crate::cx::Cx;
 use crate::obligation::graded::{ObligationToken, SemaphorePermitKind};
 use crate::sync::lock_ordering::{self, LockRank};
 use crate::types::RegionId;
+use crate::util::loom::sync::Mutex as ParkingMutex;
+use crate::util::loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 /// Error returned when semaphore acquisition fails.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
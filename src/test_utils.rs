@@ -8,6 +8,8 @@
 //! - Async test runners
 //! - Outcome assertion macros
 //! - Test types for pool-style tests
+//! - Deterministic log/trace snapshots ([`snapshot`])
+//! - Seeded property testing with shrinking ([`property`])
 //!
 //! # Example
 //! ```
@@ -21,6 +23,9 @@
 //! }
 //! ```
 
+pub mod property;
+pub mod snapshot;
+
 use crate::cx::Cx;
 use crate::lab::{LabConfig, LabRuntime};
 use crate::runtime::RuntimeBuilder;
@@ -37,6 +42,13 @@ pub use crate::test_ndjson::{
 };
 use crate::time::timeout;
 use parking_lot::Mutex;
+pub use property::{
+    CancellationPoint, ChannelOp, Gen, LabArbitrary, LabProperty, PropertyFailure, PropertyOutcome,
+    PropertyStats,
+};
+pub use snapshot::{
+    IdNormalizer, IdRule, LogCapture, SnapshotMode, TraceSummary, UPDATE_SNAPSHOTS_ENV,
+};
 use std::future::Future;
 use std::sync::{Arc, Once};
 use std::time::Duration;
@@ -200,6 +212,30 @@ where
     f(&mut lab)
 }
 
+/// Spawns a lab task in `region` that runs `body` once virtual time reaches
+/// `at_ms`.
+#[cfg(test)]
+pub(crate) fn spawn_at<F, Fut>(
+    runtime: &mut LabRuntime,
+    region: crate::types::RegionId,
+    budget: crate::types::Budget,
+    at_ms: u64,
+    body: F,
+) where
+    F: FnOnce(Cx) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (task_id, _handle) = runtime
+        .state
+        .create_task(region, budget, async move {
+            let cx = Cx::current().expect("lab task has a current Cx");
+            crate::time::sleep_until(crate::types::Time::from_millis(at_ms)).await;
+            body(cx).await;
+        })
+        .expect("create task");
+    runtime.scheduler.lock().schedule(task_id, 0);
+}
+
 /// Create a [`TestContext`] for a unit test with the default seed.
 #[must_use]
 pub fn test_context(test_id: &str) -> TestContext {
@@ -0,0 +1,866 @@
+//! Deterministic property testing on top of the lab runtime.
+//!
+//! [`LabProperty`] runs a property once per seed. The seed drives both the
+//! input generator ([`Gen`]) and the lab scheduler, so a failing case is
+//! reproduced by its seed alone. A failing input is shrunk with the seed held
+//! fixed, which keeps every shrink step on the same schedule; afterwards the
+//! schedule itself can optionally be shrunk with [`DporExplorer`].
+//!
+//! ```ignore
+//! use asupersync::test_utils::property::ChannelOp;
+//!
+//! asupersync::lab_property!(
+//!     name: "no_message_lost",
+//!     seeds: 0..64,
+//!     |lab, ops: Vec<ChannelOp>| drive_channel(lab, ops)
+//! );
+//! ```
+//!
+//! On failure the report shows the shrunk input, the seed, and a replay
+//! command. Setting `ASUPERSYNC_SEED` restricts every property to that one
+//! seed, so the command reruns exactly the failing case.
+
+use crate::lab::{DporExplorer, ExplorerConfig, LabConfig, LabRuntime};
+use crate::test_logging::{ReproManifest, derive_component_seed};
+use crate::types::{Budget, CancelKind, Time};
+use crate::util::DetRng;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Environment variable that pins properties to a single seed.
+pub const SEED_ENV: &str = "ASUPERSYNC_SEED";
+
+const INPUT_COMPONENT: &str = "property-input";
+const DEFAULT_SEEDS: Range<u64> = 0..64;
+const DEFAULT_SIZE: usize = 32;
+const DEFAULT_MAX_SHRINK_RUNS: usize = 1024;
+
+/// Deterministic source of generated values.
+///
+/// The stream is derived from the case seed, so it is independent of the
+/// scheduler's use of the same seed but reproduced by it.
+#[derive(Debug)]
+pub struct Gen {
+    rng: DetRng,
+    size: usize,
+}
+
+impl Gen {
+    /// Creates the generator for a case seed. `size` bounds collection
+    /// lengths and most numeric values.
+    #[must_use]
+    pub fn new(seed: u64, size: usize) -> Self {
+        Self {
+            rng: DetRng::new(derive_component_seed(seed, INPUT_COMPONENT)),
+            size,
+        }
+    }
+
+    /// Returns the size bound.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the underlying RNG.
+    pub fn rng(&mut self) -> &mut DetRng {
+        &mut self.rng
+    }
+
+    /// Returns a value in `0..bound`, or 0 when `bound` is 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            return 0;
+        }
+        self.rng.next_u64() % bound
+    }
+
+    /// Returns `true` with probability `numerator / denominator`.
+    pub fn ratio(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    /// Generates a value.
+    pub fn generate<T: LabArbitrary>(&mut self) -> T {
+        T::arbitrary(self)
+    }
+}
+
+/// Values that can be generated from a [`Gen`] and shrunk toward simpler
+/// values.
+pub trait LabArbitrary: Clone + fmt::Debug {
+    /// Generates a value.
+    fn arbitrary(g: &mut Gen) -> Self;
+
+    /// Returns simpler candidates, simplest first. Every candidate must be
+    /// strictly simpler than `self` so that shrinking terminates.
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+/// Candidates for an unsigned value: zero, then values approaching `value`
+/// from below by halving distances.
+fn shrink_unsigned(value: u64) -> Vec<u64> {
+    if value == 0 {
+        return Vec::new();
+    }
+    let mut candidates = vec![0];
+    let mut distance = value / 2;
+    while distance > 0 {
+        let candidate = value - distance;
+        if candidates.last() != Some(&candidate) {
+            candidates.push(candidate);
+        }
+        distance /= 2;
+    }
+    candidates
+}
+
+impl LabArbitrary for bool {
+    fn arbitrary(g: &mut Gen) -> Self {
+        g.rng.next_bool()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self { vec![false] } else { Vec::new() }
+    }
+}
+
+macro_rules! unsigned_arbitrary {
+    ($($ty:ty),*) => {$(
+        impl LabArbitrary for $ty {
+            fn arbitrary(g: &mut Gen) -> Self {
+                if g.ratio(1, 8) {
+                    g.rng.next_u64() as Self
+                } else {
+                    g.below(g.size as u64 + 1) as Self
+                }
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                shrink_unsigned(*self as u64).into_iter().map(|v| v as Self).collect()
+            }
+        }
+    )*};
+}
+
+unsigned_arbitrary!(u8, u16, u32, u64, usize);
+
+impl<T: LabArbitrary> LabArbitrary for Option<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        if g.ratio(1, 4) { None } else { Some(T::arbitrary(g)) }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            None => Vec::new(),
+            Some(value) => std::iter::once(None)
+                .chain(value.shrink().into_iter().map(Some))
+                .collect(),
+        }
+    }
+}
+
+impl<T: LabArbitrary> LabArbitrary for Vec<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = g.below(g.size as u64 + 1);
+        (0..len).map(|_| T::arbitrary(g)).collect()
+    }
+
+    /// Removes chunks, largest first, then shrinks single elements.
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        let mut chunk = self.len();
+        while chunk > 0 {
+            let mut start = 0;
+            while start < self.len() {
+                let end = (start + chunk).min(self.len());
+                let mut candidate = self[..start].to_vec();
+                candidate.extend_from_slice(&self[end..]);
+                candidates.push(candidate);
+                start += chunk;
+            }
+            chunk /= 2;
+        }
+        for (index, item) in self.iter().enumerate() {
+            for simpler in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[index] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<A: LabArbitrary, B: LabArbitrary> LabArbitrary for (A, B) {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (A::arbitrary(g), B::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let firsts = a.shrink().into_iter().map(|a| (a, b.clone()));
+        firsts.chain(b.shrink().into_iter().map(|b| (a.clone(), b))).collect()
+    }
+}
+
+/// Budgets shrink toward [`Budget::INFINITE`], one dimension at a time.
+impl LabArbitrary for Budget {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut budget = Budget::INFINITE;
+        if g.ratio(1, 2) {
+            budget.deadline = Some(Time::from_millis(g.below(g.size as u64 * 100) + 1));
+        }
+        if g.ratio(1, 2) {
+            budget.poll_quota = g.below(g.size as u64) as u32 + 1;
+        }
+        if g.ratio(1, 4) {
+            budget.cost_quota = Some(g.below(g.size as u64 * 10));
+        }
+        budget.priority = u8::arbitrary(g);
+        budget
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        if self.deadline.is_some() {
+            candidates.push(Self {
+                deadline: None,
+                ..*self
+            });
+        }
+        if self.poll_quota != u32::MAX {
+            candidates.push(Self {
+                poll_quota: u32::MAX,
+                ..*self
+            });
+        }
+        if self.cost_quota.is_some() {
+            candidates.push(Self {
+                cost_quota: None,
+                ..*self
+            });
+        }
+        if self.priority != 0 {
+            candidates.push(Self {
+                priority: 0,
+                ..*self
+            });
+        }
+        candidates
+    }
+}
+
+/// One step of a channel workload.
+///
+/// The driver decides how each step maps onto a channel; the blocking
+/// variants shrink to their simpler counterparts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelOp {
+    /// Send a value, waiting for capacity.
+    Send(u32),
+    /// Send a value without waiting.
+    TrySend(u32),
+    /// Receive a value, waiting for one to arrive.
+    Recv,
+    /// Receive a value without waiting.
+    TryRecv,
+}
+
+impl LabArbitrary for ChannelOp {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match g.below(4) {
+            0 => Self::Send(u32::arbitrary(g)),
+            1 => Self::TrySend(u32::arbitrary(g)),
+            2 => Self::Recv,
+            _ => Self::TryRecv,
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match *self {
+            Self::Send(value) => value.shrink().into_iter().map(Self::Send).collect(),
+            Self::TrySend(value) => std::iter::once(Self::Send(value))
+                .chain(value.shrink().into_iter().map(Self::TrySend))
+                .collect(),
+            Self::Recv => Vec::new(),
+            Self::TryRecv => vec![Self::Recv],
+        }
+    }
+}
+
+/// Where to cancel a task under test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancellationPoint {
+    /// Number of polls the task completes before the cancel is requested.
+    pub after_polls: u32,
+    /// Kind of cancellation to request.
+    pub kind: CancelKind,
+}
+
+const CANCEL_KINDS: [CancelKind; 5] = [
+    CancelKind::User,
+    CancelKind::Timeout,
+    CancelKind::Deadline,
+    CancelKind::ParentCancelled,
+    CancelKind::Shutdown,
+];
+
+impl LabArbitrary for CancellationPoint {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let after_polls = g.below(g.size as u64 + 1) as u32;
+        let kind = CANCEL_KINDS[g.below(CANCEL_KINDS.len() as u64) as usize];
+        Self { after_polls, kind }
+    }
+
+    /// Shrinks toward an immediate user cancel.
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        if self.kind != CancelKind::User {
+            candidates.push(Self {
+                kind: CancelKind::User,
+                ..*self
+            });
+        }
+        for after_polls in self.after_polls.shrink() {
+            candidates.push(Self {
+                after_polls,
+                ..*self
+            });
+        }
+        candidates
+    }
+}
+
+/// Conversion of a property's return value into pass or fail.
+pub trait PropertyOutcome {
+    /// Returns the failure message, if the property failed.
+    fn into_result(self) -> Result<(), String>;
+}
+
+impl PropertyOutcome for () {
+    fn into_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl PropertyOutcome for bool {
+    fn into_result(self) -> Result<(), String> {
+        if self {
+            Ok(())
+        } else {
+            Err("property returned false".to_string())
+        }
+    }
+}
+
+impl<E: fmt::Display> PropertyOutcome for Result<(), E> {
+    fn into_result(self) -> Result<(), String> {
+        self.map_err(|error| error.to_string())
+    }
+}
+
+/// Summary of a passing property run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyStats {
+    /// Cases run, one per seed.
+    pub cases: u64,
+    /// Lab scheduler steps across all cases.
+    pub lab_steps: u64,
+}
+
+/// A failing case, shrunk.
+#[derive(Debug, Clone)]
+pub struct PropertyFailure<T> {
+    /// Property name, used as the test filter in the replay command.
+    pub name: String,
+    /// Seed that generated the input and drove the scheduler.
+    pub seed: u64,
+    /// Scheduler seed for the shrunk case. Equals `seed` unless schedule
+    /// shrinking found a shorter failing schedule.
+    pub schedule_seed: u64,
+    /// Input as generated.
+    pub original: T,
+    /// Smallest failing input found.
+    pub shrunk: T,
+    /// Failure message of the shrunk case.
+    pub message: String,
+    /// Property runs spent shrinking.
+    pub shrink_runs: usize,
+}
+
+impl<T> PropertyFailure<T> {
+    /// Returns the replay artifact for the failing seed.
+    #[must_use]
+    pub fn manifest(&self) -> ReproManifest {
+        ReproManifest::new(self.seed, &self.name, false)
+            .with_failure_class("property")
+            .with_failure_reason(&self.message)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for PropertyFailure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "property `{}` failed (shrunk in {} runs)",
+            self.name, self.shrink_runs
+        )?;
+        writeln!(f, "  failure: {}", self.message)?;
+        writeln!(f, "  shrunk input: {:?}", self.shrunk)?;
+        writeln!(f, "  seed: 0x{:X}", self.seed)?;
+        if self.schedule_seed != self.seed {
+            writeln!(f, "  schedule seed: 0x{:X}", self.schedule_seed)?;
+        }
+        write!(f, "  replay: {}", self.manifest().replay_command)
+    }
+}
+
+/// Runs a property over a range of seeds and shrinks the first failure.
+#[derive(Debug, Clone)]
+pub struct LabProperty {
+    name: String,
+    seeds: Range<u64>,
+    size: usize,
+    max_shrink_runs: usize,
+    schedule_runs: usize,
+}
+
+impl LabProperty {
+    /// Creates a runner; `name` should be the test name so the replay command
+    /// selects it.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            seeds: DEFAULT_SEEDS,
+            size: DEFAULT_SIZE,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            schedule_runs: 0,
+        }
+    }
+
+    /// Sets the seeds to run (default `0..64`).
+    #[must_use]
+    pub fn seeds(mut self, seeds: Range<u64>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    /// Sets the generator size bound (default 32).
+    #[must_use]
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Caps the property runs spent shrinking the input (default 1024).
+    #[must_use]
+    pub fn max_shrink_runs(mut self, runs: usize) -> Self {
+        self.max_shrink_runs = runs;
+        self
+    }
+
+    /// After shrinking the input, explores up to `runs` schedules with
+    /// [`DporExplorer`] and keeps the failing one with the fewest steps.
+    #[must_use]
+    pub fn shrink_schedules(mut self, runs: usize) -> Self {
+        self.schedule_runs = runs;
+        self
+    }
+
+    /// Runs the property on every seed, stopping at the first failure.
+    ///
+    /// The property gets a fresh [`LabRuntime`] seeded with the case seed.
+    /// Panics inside the property count as failures.
+    pub fn run<T, F, O>(&self, property: F) -> Result<PropertyStats, PropertyFailure<T>>
+    where
+        T: LabArbitrary,
+        F: Fn(&mut LabRuntime, &T) -> O,
+        O: PropertyOutcome,
+    {
+        let seeds = match seed_from_env() {
+            Some(seed) => seed..seed.saturating_add(1),
+            None => self.seeds.clone(),
+        };
+        let mut stats = PropertyStats {
+            cases: 0,
+            lab_steps: 0,
+        };
+        for seed in seeds {
+            let input: T = Gen::new(seed, self.size).generate();
+            let (result, steps) = run_case(seed, &input, &property);
+            stats.cases += 1;
+            stats.lab_steps += steps;
+            if let Err(message) = result {
+                return Err(self.shrink(seed, input, message, steps, &property));
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Runs one case with a given input, for replaying a shrunk failure.
+    pub fn replay<T, F, O>(seed: u64, input: &T, property: F) -> Result<(), String>
+    where
+        F: Fn(&mut LabRuntime, &T) -> O,
+        O: PropertyOutcome,
+    {
+        run_case(seed, input, &property).0
+    }
+
+    fn shrink<T, F, O>(
+        &self,
+        seed: u64,
+        original: T,
+        mut message: String,
+        mut steps: u64,
+        property: &F,
+    ) -> PropertyFailure<T>
+    where
+        T: LabArbitrary,
+        F: Fn(&mut LabRuntime, &T) -> O,
+        O: PropertyOutcome,
+    {
+        let mut shrunk = original.clone();
+        let mut shrink_runs = 0;
+        'search: while shrink_runs < self.max_shrink_runs {
+            for candidate in shrunk.shrink() {
+                if shrink_runs == self.max_shrink_runs {
+                    break 'search;
+                }
+                shrink_runs += 1;
+                let (result, candidate_steps) = run_case(seed, &candidate, property);
+                if let Err(candidate_message) = result {
+                    shrunk = candidate;
+                    message = candidate_message;
+                    steps = candidate_steps;
+                    continue 'search;
+                }
+            }
+            break;
+        }
+
+        let mut schedule_seed = seed;
+        let schedule = (self.schedule_runs > 0)
+            .then(|| self.shrink_schedule(seed, &shrunk, steps, property))
+            .flatten();
+        if let Some((found, found_message)) = schedule {
+            schedule_seed = found;
+            message = found_message;
+        }
+        PropertyFailure {
+            name: self.name.clone(),
+            seed,
+            schedule_seed,
+            original,
+            shrunk,
+            message,
+            shrink_runs,
+        }
+    }
+
+    fn shrink_schedule<T, F, O>(
+        &self,
+        seed: u64,
+        input: &T,
+        steps: u64,
+        property: &F,
+    ) -> Option<(u64, String)>
+    where
+        F: Fn(&mut LabRuntime, &T) -> O,
+        O: PropertyOutcome,
+    {
+        let failing = RefCell::new(BTreeMap::new());
+        let mut explorer = DporExplorer::new(ExplorerConfig::new(seed, self.schedule_runs));
+        let report = explorer.explore(|lab| {
+            if let Err(message) = check(lab, input, property) {
+                failing.borrow_mut().insert(lab.config().seed, message);
+            }
+        });
+        let failing = failing.into_inner();
+        let shortest = report
+            .runs
+            .iter()
+            .filter(|run| run.steps < steps && failing.contains_key(&run.seed))
+            .min_by_key(|run| (run.steps, run.seed))?;
+        // The explorer configures its runtimes itself; keep the schedule only
+        // if it also fails under the plain configuration used for replay.
+        let message = run_case(shortest.seed, input, property).0.err()?;
+        Some((shortest.seed, message))
+    }
+}
+
+fn run_case<T, F, O>(seed: u64, input: &T, property: &F) -> (Result<(), String>, u64)
+where
+    F: Fn(&mut LabRuntime, &T) -> O,
+    O: PropertyOutcome,
+{
+    let mut lab = LabRuntime::new(LabConfig::new(seed));
+    let result = check(&mut lab, input, property);
+    (result, lab.steps())
+}
+
+fn check<T, F, O>(lab: &mut LabRuntime, input: &T, property: &F) -> Result<(), String>
+where
+    F: Fn(&mut LabRuntime, &T) -> O,
+    O: PropertyOutcome,
+{
+    match panic::catch_unwind(AssertUnwindSafe(|| property(lab, input))) {
+        Ok(outcome) => outcome.into_result(),
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+fn seed_from_env() -> Option<u64> {
+    let value = std::env::var(SEED_ENV).ok()?;
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Runs a property over lab seeds and panics with the shrunk failure.
+///
+/// ```ignore
+/// lab_property!(seeds: 0..32, |lab, ops: Vec<ChannelOp>| check_fifo(lab, ops));
+/// ```
+///
+/// The closure receives `&mut LabRuntime` and a reference to the generated
+/// input, and returns `()`, `bool`, or `Result<(), impl Display>`. Without
+/// `name:`, the module path is used.
+#[macro_export]
+macro_rules! lab_property {
+    (
+        name: $name:expr,
+        seeds: $seeds:expr,
+        |$lab:ident, $input:ident : $ty:ty| $body:expr $(,)?
+    ) => {{
+        let property = $crate::test_utils::property::LabProperty::new($name).seeds($seeds);
+        let result = property.run(|$lab: &mut $crate::lab::LabRuntime, $input: &$ty| $body);
+        if let Err(failure) = result {
+            panic!("{failure}");
+        }
+    }};
+    (seeds: $seeds:expr, |$lab:ident, $input:ident : $ty:ty| $body:expr $(,)?) => {
+        $crate::lab_property!(
+            name: module_path!(),
+            seeds: $seeds,
+            |$lab, $input: $ty| $body
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::channel::mpsc;
+    use std::cell::Cell;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Applies `ops` to a capacity-2 channel inside a lab task and checks
+    /// that every sent value is received. The lossy variant forgets that
+    /// `try_send` can fail, so any three sends without a receive between
+    /// them break the property.
+    fn drive_channel(lab: &mut LabRuntime, ops: &[ChannelOp], lossy: bool) -> Result<(), String> {
+        let ops = ops.to_vec();
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let (task, mut handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let (tx, mut rx) = mpsc::channel::<u32>(2);
+                let mut sent = Vec::new();
+                let mut received = Vec::new();
+                for op in ops {
+                    match op {
+                        ChannelOp::Send(value) | ChannelOp::TrySend(value) => {
+                            if tx.try_send(value).is_ok() || lossy {
+                                sent.push(value);
+                            }
+                        }
+                        ChannelOp::Recv | ChannelOp::TryRecv => {
+                            if let Ok(value) = rx.try_recv() {
+                                received.push(value);
+                            }
+                        }
+                    }
+                }
+                drop(tx);
+                while let Ok(value) = rx.try_recv() {
+                    received.push(value);
+                }
+                (sent, received)
+            })
+            .expect("create channel task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_until_quiescent();
+        let (sent, received) = handle
+            .try_join()
+            .expect("join channel task")
+            .expect("channel task completed");
+        if sent == received {
+            Ok(())
+        } else {
+            Err(format!("sent {sent:?}, received {received:?}"))
+        }
+    }
+
+    fn lossy_failure(property: &LabProperty) -> PropertyFailure<Vec<ChannelOp>> {
+        property
+            .run(|lab, ops: &Vec<ChannelOp>| drive_channel(lab, ops, true))
+            .expect_err("lossy driver loses messages")
+    }
+
+    #[test]
+    fn failing_property_shrinks_to_minimal_counterexample() {
+        init_test("failing_property_shrinks_to_minimal_counterexample");
+        let property = LabProperty::new("lossy_channel").seeds(0..16);
+        let first = lossy_failure(&property);
+        let second = lossy_failure(&property);
+
+        // Three sends fill the capacity-2 channel and drop the third value;
+        // no receive is needed and every value shrinks to zero.
+        let minimal = vec![ChannelOp::Send(0); 3];
+        crate::assert_with_log!(first.shrunk == minimal, "minimal", minimal, first.shrunk);
+        let message = "sent [0, 0, 0], received [0, 0]";
+        crate::assert_with_log!(first.message == message, "message", message, first.message);
+        let grew = first.original.len() >= minimal.len();
+        crate::assert_with_log!(grew, "original at least minimal", true, first.original);
+
+        let same = first.seed == second.seed
+            && first.shrunk == second.shrunk
+            && first.shrink_runs == second.shrink_runs;
+        crate::assert_with_log!(same, "deterministic", first.seed, second.seed);
+        crate::test_complete!("failing_property_shrinks_to_minimal_counterexample");
+    }
+
+    #[test]
+    fn shrunk_case_replays_consistently() {
+        init_test("shrunk_case_replays_consistently");
+        let failure = lossy_failure(&LabProperty::new("lossy_channel").seeds(0..16));
+        for _ in 0..3 {
+            let replay = LabProperty::replay(failure.seed, &failure.shrunk, |lab, ops| {
+                drive_channel(lab, ops, true)
+            });
+            let expected = Err(failure.message.clone());
+            crate::assert_with_log!(replay == expected, "replay fails", expected, replay);
+        }
+
+        let with_schedules = lossy_failure(
+            &LabProperty::new("lossy_channel")
+                .seeds(0..16)
+                .shrink_schedules(4),
+        );
+        crate::assert_with_log!(
+            with_schedules.shrunk == failure.shrunk,
+            "schedule shrinking keeps input",
+            failure.shrunk,
+            with_schedules.shrunk
+        );
+        let replay = LabProperty::replay(
+            with_schedules.schedule_seed,
+            &with_schedules.shrunk,
+            |lab, ops| drive_channel(lab, ops, true),
+        );
+        crate::assert_with_log!(replay.is_err(), "schedule replay fails", true, replay);
+
+        let report = failure.to_string();
+        let shows_input = report.contains("shrunk input: [Send(0), Send(0), Send(0)]");
+        crate::assert_with_log!(shows_input, "report shows input", true, report);
+        let seed = format!("ASUPERSYNC_SEED=0x{:X}", failure.seed);
+        let shows_seed = report.contains(&seed);
+        crate::assert_with_log!(shows_seed, "report shows seed", seed, report);
+        crate::test_complete!("shrunk_case_replays_consistently");
+    }
+
+    #[test]
+    fn passing_property_adds_no_lab_work() {
+        init_test("passing_property_adds_no_lab_work");
+        let calls = Cell::new(0_u64);
+        let stats = LabProperty::new("fifo_channel")
+            .seeds(0..32)
+            .run(|lab, ops: &Vec<ChannelOp>| {
+                calls.set(calls.get() + 1);
+                drive_channel(lab, ops, false)
+            })
+            .expect("backpressure-aware driver passes");
+        crate::assert_with_log!(stats.cases == 32, "cases", 32, stats.cases);
+        crate::assert_with_log!(calls.get() == 32, "one run per seed", 32, calls.get());
+
+        let mut direct_steps = 0;
+        for seed in 0..32 {
+            let ops: Vec<ChannelOp> = Gen::new(seed, DEFAULT_SIZE).generate();
+            let mut lab = LabRuntime::new(LabConfig::new(seed));
+            drive_channel(&mut lab, &ops, false).expect("direct run passes");
+            direct_steps += lab.steps();
+        }
+        crate::assert_with_log!(
+            stats.lab_steps == direct_steps,
+            "same scheduler work as direct runs",
+            direct_steps,
+            stats.lab_steps
+        );
+        crate::test_complete!("passing_property_adds_no_lab_work");
+    }
+
+    #[test]
+    fn builtin_generators_shrink_to_simplest_values() {
+        init_test("builtin_generators_shrink_to_simplest_values");
+        let first: (Budget, CancellationPoint) = Gen::new(5, DEFAULT_SIZE).generate();
+        let again: (Budget, CancellationPoint) = Gen::new(5, DEFAULT_SIZE).generate();
+        crate::assert_with_log!(first == again, "generation is seeded", first, again);
+
+        let failure = LabProperty::new("always_fails")
+            .seeds(0..1)
+            .run(|_, _: &(Budget, CancellationPoint)| false)
+            .expect_err("property always fails");
+        let simplest = (
+            Budget::INFINITE,
+            CancellationPoint {
+                after_polls: 0,
+                kind: CancelKind::User,
+            },
+        );
+        crate::assert_with_log!(failure.shrunk == simplest, "simplest", simplest, failure.shrunk);
+        crate::test_complete!("builtin_generators_shrink_to_simplest_values");
+    }
+
+    #[test]
+    #[should_panic(expected = "shrunk input: [Send(0), Send(0), Send(0)]")]
+    fn lab_property_macro_reports_shrunk_input() {
+        crate::lab_property!(
+            name: "lab_property_macro_reports_shrunk_input",
+            seeds: 0..16,
+            |lab, ops: Vec<ChannelOp>| drive_channel(lab, ops, true)
+        );
+    }
+}
@@ -0,0 +1,1197 @@
+//! Deterministic snapshot helpers for log and trace assertions.
+//!
+//! Lab runs are deterministic, but the text they produce is not directly
+//! comparable across runs: span ids come from a process-global counter, trace
+//! ids are derived from entropy, and arena indices shift whenever a test adds
+//! or removes a task. This module turns that output into stable text:
+//!
+//! - [`LogCapture`] renders collected [`LogEntry`] values one per line with
+//!   sorted fields, passing every line through an [`IdNormalizer`].
+//! - [`IdNormalizer`] replaces runtime identifiers with `label-N` names in
+//!   first-seen order (`T7` → `task-1`, a W3C trace id → `trace-1`). Tests can
+//!   register extra [`IdRule`]s for their own identifiers.
+//! - [`TraceSummary`] condenses a trace into per-task event counts, the region
+//!   open/close ordering and obligation lifecycle counts.
+//! - [`assert_snapshot!`](crate::assert_snapshot) compares text against a
+//!   `.snap` file next to the test, creating it on first run. Set
+//!   `UPDATE_SNAPSHOTS=1` to rewrite mismatching snapshots in place.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::assert_snapshot;
+//! use asupersync::test_utils::snapshot::{LogCapture, TraceSummary};
+//!
+//! let mut runtime = LabRuntime::new(LabConfig::new(42));
+//! let capture = LogCapture::install(&mut runtime);
+//! // ... spawn tasks that call `cx.log(..)`, run to quiescence ...
+//! assert_snapshot!("worker_logs", capture.render());
+//! assert_snapshot!("worker_trace", TraceSummary::from_lab(&runtime));
+//! ```
+
+use crate::cx::Cx;
+use crate::lab::LabRuntime;
+use crate::observability::{LogCollector, LogEntry, ObservabilityConfig};
+use crate::trace::{TraceData, TraceEvent, TraceEventKind};
+use crate::types::{RegionId, TaskId};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Environment variable that switches [`assert_snapshot!`](crate::assert_snapshot)
+/// into update mode.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Header prefix written at the top of every snapshot file.
+const SNAPSHOT_HEADER: &str = "# asupersync snapshot: ";
+
+/// Lines of unchanged context shown around each diff hunk.
+const DIFF_CONTEXT: usize = 2;
+
+/// Upper bound on the LCS table size before the diff falls back to a
+/// whole-block replacement.
+const DIFF_MAX_CELLS: usize = 4_000_000;
+
+type IdMatcher = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A rule recognising one kind of identifier inside rendered text.
+///
+/// Rules are applied to maximal runs of ASCII alphanumeric characters, so a
+/// matcher sees `T12` or a 32-digit hex string, never surrounding punctuation.
+#[derive(Clone)]
+pub struct IdRule {
+    label: String,
+    matcher: IdMatcher,
+}
+
+impl IdRule {
+    /// Creates a rule from an arbitrary token predicate.
+    pub fn new(
+        label: impl Into<String>,
+        matcher: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            matcher: Arc::new(matcher),
+        }
+    }
+
+    /// Matches `prefix` followed by one or more ASCII digits (e.g. `T12`).
+    pub fn prefixed(label: impl Into<String>, prefix: &'static str) -> Self {
+        Self::new(label, move |token| {
+            token
+                .strip_prefix(prefix)
+                .is_some_and(|rest| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()))
+        })
+    }
+
+    /// Matches lowercase hex strings of exactly `len` characters.
+    pub fn hex(label: impl Into<String>, len: usize) -> Self {
+        Self::new(label, move |token| {
+            token.len() == len
+                && token
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        })
+    }
+
+    /// Returns the label used for normalized ids.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn matches(&self, token: &str) -> bool {
+        (self.matcher)(token)
+    }
+}
+
+impl fmt::Debug for IdRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdRule")
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Substitution map that rewrites runtime identifiers into stable names.
+///
+/// Every distinct raw id gets `label-N`, numbered from 1 in the order the
+/// normalizer first sees it. Rendering the same run twice therefore produces
+/// identical text even though span and trace ids differ between processes.
+#[derive(Debug, Clone)]
+pub struct IdNormalizer {
+    rules: Vec<IdRule>,
+    fields: BTreeMap<String, String>,
+    assigned: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl IdNormalizer {
+    /// Creates a normalizer with the runtime's default id rules.
+    ///
+    /// The defaults cover W3C trace ids (32 lowercase hex digits) and the
+    /// display forms of span (`S1`), task (`T1`), region (`R1`) and obligation
+    /// (`O1`) ids.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::empty()
+            .with_rule(IdRule::hex("trace", 32))
+            .with_rule(IdRule::prefixed("span", "S"))
+            .with_rule(IdRule::prefixed("task", "T"))
+            .with_rule(IdRule::prefixed("region", "R"))
+            .with_rule(IdRule::prefixed("obligation", "O"))
+    }
+
+    /// Creates a normalizer without any rules.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            rules: Vec::new(),
+            fields: BTreeMap::new(),
+            assigned: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a token rule.
+    ///
+    /// Rules added later are tried first, so a test-specific rule takes
+    /// precedence over the defaults.
+    #[must_use]
+    pub fn with_rule(mut self, rule: IdRule) -> Self {
+        self.rules.insert(0, rule);
+        self
+    }
+
+    /// Treats the whole value of log field `key` as an id labelled `label`.
+    #[must_use]
+    pub fn with_field(mut self, key: impl Into<String>, label: impl Into<String>) -> Self {
+        self.fields.insert(key.into(), label.into());
+        self
+    }
+
+    /// Returns the stable name for `raw`, assigning the next number if unseen.
+    pub fn id(&mut self, label: &str, raw: &str) -> String {
+        let ids = self.assigned.entry(label.to_string()).or_default();
+        let next = ids.len() + 1;
+        let n = *ids.entry(raw.to_string()).or_insert(next);
+        format!("{label}-{n}")
+    }
+
+    /// Rewrites every token in `text` that matches a rule.
+    pub fn normalize(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            let split = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            if split == 0 {
+                let c = rest.chars().next().expect("non-empty");
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            let (token, tail) = rest.split_at(split);
+            let label = self
+                .rules
+                .iter()
+                .find(|rule| rule.matches(token))
+                .map(|rule| rule.label.clone());
+            match label {
+                Some(label) => out.push_str(&self.id(&label, token)),
+                None => out.push_str(token),
+            }
+            rest = tail;
+        }
+        out
+    }
+
+    /// Normalizes the value of log field `key`, honouring field rules.
+    pub fn normalize_field(&mut self, key: &str, value: &str) -> String {
+        match self.fields.get(key).cloned() {
+            Some(label) => self.id(&label, value),
+            None => self.normalize(value),
+        }
+    }
+}
+
+impl Default for IdNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Captures structured log entries and renders them deterministically.
+#[derive(Debug, Clone)]
+pub struct LogCapture {
+    collector: LogCollector,
+    timestamps: bool,
+}
+
+impl LogCapture {
+    /// Creates a capture with a fresh testing collector.
+    ///
+    /// Attach it to contexts with [`LogCapture::attach`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_collector(ObservabilityConfig::testing().create_collector())
+    }
+
+    /// Wraps an existing collector.
+    #[must_use]
+    pub fn from_collector(collector: LogCollector) -> Self {
+        Self {
+            collector,
+            timestamps: true,
+        }
+    }
+
+    /// Enables testing observability on `runtime` and captures its logs.
+    ///
+    /// Every task created afterwards logs into the returned capture.
+    pub fn install(runtime: &mut LabRuntime) -> Self {
+        runtime
+            .state
+            .set_observability_config(ObservabilityConfig::testing());
+        let collector = runtime
+            .state
+            .log_collector()
+            .expect("observability config was just installed");
+        Self::from_collector(collector)
+    }
+
+    /// Routes `cx`'s log entries into this capture.
+    pub fn attach(&self, cx: &Cx) {
+        cx.set_log_collector(self.collector.clone());
+    }
+
+    /// Returns the underlying collector.
+    #[must_use]
+    pub fn collector(&self) -> &LogCollector {
+        &self.collector
+    }
+
+    /// Omits timestamps from rendered lines.
+    ///
+    /// Lab timestamps come from the virtual clock and are stable; use this for
+    /// captures fed by wall-clock contexts.
+    #[must_use]
+    pub fn without_timestamps(mut self) -> Self {
+        self.timestamps = false;
+        self
+    }
+
+    /// Returns the captured entries in log order.
+    #[must_use]
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.collector.peek()
+    }
+
+    /// Renders the captured entries with the default normalizer.
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.render_with(&mut IdNormalizer::new())
+    }
+
+    /// Renders the captured entries, one line each.
+    ///
+    /// The line format is `<timestamp> <LEVEL> <message>` followed by
+    /// ` key=value` pairs sorted by key. Values that are empty or contain
+    /// whitespace, `=` or `"` are quoted.
+    pub fn render_with(&self, normalizer: &mut IdNormalizer) -> String {
+        let mut out = String::new();
+        for entry in self.entries() {
+            if self.timestamps {
+                out.push_str(&entry.timestamp().to_string());
+                out.push(' ');
+            }
+            out.push_str(&entry.level().to_string());
+            out.push(' ');
+            out.push_str(&escape(&normalizer.normalize(entry.message())));
+            let mut fields: Vec<(&str, &str)> = entry.fields().collect();
+            fields.sort_unstable();
+            for (key, value) in fields {
+                out.push(' ');
+                out.push_str(key);
+                out.push('=');
+                out.push_str(&quote(&normalizer.normalize_field(key, value)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Default for LogCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn quote(value: &str) -> String {
+    let escaped = escape(value);
+    if escaped.is_empty() || escaped.contains(|c: char| c.is_whitespace() || c == '=' || c == '"') {
+        format!("\"{}\"", escaped.replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
+/// Obligation lifecycle counts within a trace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObligationCounts {
+    /// `obligation_reserve` events.
+    pub reserved: usize,
+    /// `obligation_commit` events.
+    pub committed: usize,
+    /// `obligation_abort` events.
+    pub aborted: usize,
+    /// `obligation_leak` events.
+    pub leaked: usize,
+}
+
+/// One region lifecycle event, in trace order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionTransition {
+    /// The region.
+    pub region: RegionId,
+    /// The parent region, when the event carries it.
+    pub parent: Option<RegionId>,
+    /// The event kind (`region_created`, `region_close_begin`, ...).
+    pub kind: TraceEventKind,
+}
+
+/// A compact, order-preserving digest of a trace.
+///
+/// Raw traces record every poll and wake, which makes them brittle as
+/// snapshots. The summary keeps what structured-concurrency tests usually
+/// assert on: how many events each task produced, the order regions opened
+/// and closed in, and whether every reserved obligation was resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceSummary {
+    /// Number of events summarized.
+    pub total_events: usize,
+    /// Event counts by stable kind name.
+    pub event_counts: BTreeMap<&'static str, usize>,
+    /// Per-task event counts, in order of each task's first event.
+    pub tasks: Vec<(TaskId, BTreeMap<&'static str, usize>)>,
+    /// Region lifecycle events in trace order.
+    pub regions: Vec<RegionTransition>,
+    /// Obligation lifecycle counts.
+    pub obligations: ObligationCounts,
+}
+
+impl TraceSummary {
+    /// Summarizes `events` in the order given.
+    #[must_use]
+    pub fn from_events(events: &[TraceEvent]) -> Self {
+        let mut summary = Self {
+            total_events: events.len(),
+            ..Self::default()
+        };
+        for event in events {
+            let name = event.kind.stable_name();
+            *summary.event_counts.entry(name).or_default() += 1;
+
+            if let Some(task) = event_task(&event.data) {
+                let slot = match summary.tasks.iter().position(|(id, _)| *id == task) {
+                    Some(slot) => slot,
+                    None => {
+                        summary.tasks.push((task, BTreeMap::new()));
+                        summary.tasks.len() - 1
+                    }
+                };
+                *summary.tasks[slot].1.entry(name).or_default() += 1;
+            }
+
+            match (event.kind, &event.data) {
+                (
+                    TraceEventKind::RegionCreated
+                    | TraceEventKind::RegionCloseBegin
+                    | TraceEventKind::RegionCloseComplete,
+                    TraceData::Region { region, parent },
+                ) => summary.regions.push(RegionTransition {
+                    region: *region,
+                    parent: *parent,
+                    kind: event.kind,
+                }),
+                (TraceEventKind::RegionCancelled, TraceData::RegionCancel { region, .. }) => {
+                    summary.regions.push(RegionTransition {
+                        region: *region,
+                        parent: None,
+                        kind: event.kind,
+                    });
+                }
+                (TraceEventKind::ObligationReserve, _) => summary.obligations.reserved += 1,
+                (TraceEventKind::ObligationCommit, _) => summary.obligations.committed += 1,
+                (TraceEventKind::ObligationAbort, _) => summary.obligations.aborted += 1,
+                (TraceEventKind::ObligationLeak, _) => summary.obligations.leaked += 1,
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    /// Summarizes the trace recorded by a lab runtime.
+    #[must_use]
+    pub fn from_lab(runtime: &LabRuntime) -> Self {
+        Self::from_events(&runtime.trace().snapshot())
+    }
+
+    /// Renders the summary with the default normalizer.
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.render_with(&mut IdNormalizer::new())
+    }
+
+    /// Renders the summary as stable multi-line text.
+    pub fn render_with(&self, normalizer: &mut IdNormalizer) -> String {
+        let mut out = format!("events: {}\n", self.total_events);
+        for (name, count) in &self.event_counts {
+            out.push_str(&format!("  {name}: {count}\n"));
+        }
+        out.push_str("tasks:\n");
+        for (task, counts) in &self.tasks {
+            let task = normalizer.normalize(&task.to_string());
+            let counts = counts
+                .iter()
+                .map(|(name, count)| format!("{name}={count}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            out.push_str(&format!("  {task}: {counts}\n"));
+        }
+        out.push_str("regions:\n");
+        for transition in &self.regions {
+            let region = normalizer.normalize(&transition.region.to_string());
+            out.push_str(&format!("  {} {region}", transition.kind.stable_name()));
+            if let Some(parent) = transition.parent {
+                let parent = normalizer.normalize(&parent.to_string());
+                out.push_str(&format!(" parent={parent}"));
+            }
+            out.push('\n');
+        }
+        let o = &self.obligations;
+        out.push_str(&format!(
+            "obligations: reserved={} committed={} aborted={} leaked={}\n",
+            o.reserved, o.committed, o.aborted, o.leaked
+        ));
+        out
+    }
+}
+
+impl fmt::Display for TraceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+fn event_task(data: &TraceData) -> Option<TaskId> {
+    match data {
+        TraceData::Task { task, .. }
+        | TraceData::Obligation { task, .. }
+        | TraceData::Cancel { task, .. }
+        | TraceData::Worker { task, .. }
+        | TraceData::Futurelock { task, .. }
+        | TraceData::Budget { task, .. } => Some(*task),
+        TraceData::Chaos { task, .. } => *task,
+        _ => None,
+    }
+}
+
+/// Whether snapshot mismatches fail or rewrite the stored file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// Compare against the stored snapshot; create it if missing.
+    Check,
+    /// Overwrite the stored snapshot with the actual value.
+    Update,
+}
+
+impl SnapshotMode {
+    /// Reads the mode from [`UPDATE_SNAPSHOTS_ENV`].
+    ///
+    /// Any value other than empty or `0` selects [`SnapshotMode::Update`].
+    #[must_use]
+    pub fn from_env() -> Self {
+        match std::env::var(UPDATE_SNAPSHOTS_ENV) {
+            Ok(value) if !value.is_empty() && value != "0" => Self::Update,
+            _ => Self::Check,
+        }
+    }
+}
+
+/// Result of a successful snapshot check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The stored snapshot matched.
+    Matched,
+    /// No snapshot existed; one was written.
+    Created,
+    /// The stored snapshot differed and was rewritten (update mode).
+    Updated,
+}
+
+/// Snapshot check failure.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The stored snapshot differs from the actual value.
+    Mismatch {
+        /// Path of the stored snapshot.
+        path: PathBuf,
+        /// Line diff from stored to actual.
+        diff: String,
+    },
+    /// Reading or writing the snapshot file failed.
+    Io {
+        /// Path of the snapshot file.
+        path: PathBuf,
+        /// Underlying error.
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch { path, diff } => write!(
+                f,
+                "snapshot mismatch: {}\n{diff}\nrerun with {UPDATE_SNAPSHOTS_ENV}=1 to accept the new output",
+                path.display()
+            ),
+            Self::Io { path, source } => {
+                write!(f, "snapshot io error at {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Mismatch { .. } => None,
+            Self::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Returns the snapshot path for `name` in a test source file.
+///
+/// Snapshots live in a `snapshots/` directory next to the source file, named
+/// `<file stem>__<name>.snap`. Characters outside `[A-Za-z0-9_-]` in `name`
+/// become `_`.
+#[must_use]
+pub fn snapshot_path(source_file: &Path, name: &str) -> PathBuf {
+    let dir = source_file.parent().unwrap_or_else(|| Path::new("."));
+    let stem = source_file
+        .file_stem()
+        .map_or_else(|| "snapshot".into(), |stem| stem.to_string_lossy());
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join("snapshots").join(format!("{stem}__{name}.snap"))
+}
+
+/// Compares `actual` with the snapshot stored at `path`.
+///
+/// A missing snapshot is always written. On mismatch in
+/// [`SnapshotMode::Check`] the stored file is left untouched and the actual
+/// value is written to `<path>.new` for inspection.
+pub fn check_snapshot(
+    path: &Path,
+    name: &str,
+    actual: &str,
+    mode: SnapshotMode,
+) -> Result<SnapshotOutcome, SnapshotError> {
+    let io_err = |source| SnapshotError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut body = actual.to_string();
+    if !body.ends_with('\n') {
+        body.push('\n');
+    }
+    let contents = format!("{SNAPSHOT_HEADER}{name}\n{body}");
+
+    let stored = match fs::read_to_string(path) {
+        Ok(stored) => stored,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(io_err)?;
+            }
+            fs::write(path, contents).map_err(io_err)?;
+            return Ok(SnapshotOutcome::Created);
+        }
+        Err(err) => return Err(io_err(err)),
+    };
+    let expected = stored
+        .split_once('\n')
+        .filter(|(header, _)| header.starts_with(SNAPSHOT_HEADER))
+        .map_or(stored.as_str(), |(_, rest)| rest);
+    if expected == body {
+        return Ok(SnapshotOutcome::Matched);
+    }
+
+    match mode {
+        SnapshotMode::Update => {
+            fs::write(path, contents).map_err(io_err)?;
+            Ok(SnapshotOutcome::Updated)
+        }
+        SnapshotMode::Check => {
+            let mut pending = path.as_os_str().to_owned();
+            pending.push(".new");
+            fs::write(PathBuf::from(pending), contents).map_err(io_err)?;
+            Err(SnapshotError::Mismatch {
+                path: path.to_path_buf(),
+                diff: diff_lines(expected, &body),
+            })
+        }
+    }
+}
+
+/// Checks a snapshot using the mode from the environment, panicking on failure.
+///
+/// This is the function behind [`assert_snapshot!`](crate::assert_snapshot).
+#[track_caller]
+pub fn assert_snapshot_file(path: &Path, name: &str, actual: &str) {
+    match check_snapshot(path, name, actual, SnapshotMode::from_env()) {
+        Ok(SnapshotOutcome::Matched) => {}
+        Ok(SnapshotOutcome::Created) => {
+            tracing::info!(snapshot = %path.display(), "created snapshot");
+        }
+        Ok(SnapshotOutcome::Updated) => {
+            tracing::info!(snapshot = %path.display(), "updated snapshot");
+        }
+        Err(err) => panic!("{err}"),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Renders a unified-style line diff from `expected` to `actual`.
+///
+/// Hunks carry two lines of context. Inputs whose differing middle section is
+/// too large for the LCS table are shown as a single replacement hunk.
+#[must_use]
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(DiffOp, &str)> = old[..prefix].iter().map(|l| (DiffOp::Equal, *l)).collect();
+    ops.extend(diff_middle(old_mid, new_mid));
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|l| (DiffOp::Equal, *l)),
+    );
+
+    let mut out = String::from("--- expected\n+++ actual\n");
+    let mut i = 0;
+    while i < ops.len() {
+        if ops[i].0 == DiffOp::Equal {
+            i += 1;
+            continue;
+        }
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let mut last_change = i;
+        let mut j = i;
+        while j < ops.len() {
+            if ops[j].0 != DiffOp::Equal {
+                last_change = j;
+            } else if j - last_change > 2 * DIFF_CONTEXT {
+                break;
+            }
+            j += 1;
+        }
+        let stop = (last_change + DIFF_CONTEXT + 1).min(ops.len());
+
+        let old_before = ops[..start]
+            .iter()
+            .filter(|(op, _)| *op != DiffOp::Insert)
+            .count();
+        let new_before = ops[..start]
+            .iter()
+            .filter(|(op, _)| *op != DiffOp::Delete)
+            .count();
+        let hunk = &ops[start..stop];
+        let old_len = hunk.iter().filter(|(op, _)| *op != DiffOp::Insert).count();
+        let new_len = hunk.iter().filter(|(op, _)| *op != DiffOp::Delete).count();
+        let old_start = if old_len == 0 {
+            old_before
+        } else {
+            old_before + 1
+        };
+        let new_start = if new_len == 0 {
+            new_before
+        } else {
+            new_before + 1
+        };
+        out.push_str(&format!(
+            "@@ -{old_start},{old_len} +{new_start},{new_len} @@\n"
+        ));
+        for (op, line) in hunk {
+            let marker = match op {
+                DiffOp::Equal => ' ',
+                DiffOp::Delete => '-',
+                DiffOp::Insert => '+',
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+        i = stop;
+    }
+    out
+}
+
+fn diff_middle<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffOp, &'a str)> {
+    let replace = || {
+        old.iter()
+            .map(|l| (DiffOp::Delete, *l))
+            .chain(new.iter().map(|l| (DiffOp::Insert, *l)))
+            .collect()
+    };
+    let width = new.len() + 1;
+    if (old.len() + 1).saturating_mul(width) > DIFF_MAX_CELLS {
+        return replace();
+    }
+
+    // lcs[i * width + j] = LCS length of old[i..] and new[j..].
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((DiffOp::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push((DiffOp::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffOp::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|l| (DiffOp::Delete, *l)));
+    ops.extend(new[j..].iter().map(|l| (DiffOp::Insert, *l)));
+    ops
+}
+
+/// Asserts that a value's `Display` output matches a stored snapshot.
+///
+/// The snapshot lives at `snapshots/<file stem>__<name>.snap` beside the
+/// calling source file. A missing snapshot is created; a mismatch panics with
+/// a line diff and leaves the new output in a `.snap.new` file. Set
+/// `UPDATE_SNAPSHOTS=1` to overwrite mismatching snapshots instead.
+///
+/// ```ignore
+/// asupersync::assert_snapshot!("fanout_trace", TraceSummary::from_lab(&runtime));
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $value:expr $(,)?) => {{
+        let source = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(file!());
+        let name: &str = $name;
+        let path = $crate::test_utils::snapshot::snapshot_path(&source, name);
+        let actual = ::std::string::ToString::to_string(&$value);
+        $crate::test_utils::snapshot::assert_snapshot_file(&path, name, &actual);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::LabConfig;
+    use crate::observability::TraceId;
+    use crate::record::ObligationKind;
+    use crate::types::{Budget, ObligationId, Time};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn task(n: u32) -> TaskId {
+        TaskId::new_for_test(n, 1)
+    }
+
+    fn region(n: u32) -> RegionId {
+        RegionId::new_for_test(n, 1)
+    }
+
+    fn run_logging_lab(seed: u64) -> (LabRuntime, LogCapture) {
+        let mut runtime = LabRuntime::new(LabConfig::new(seed));
+        let capture = LogCapture::install(&mut runtime);
+        let root = runtime.state.create_root_region(Budget::INFINITE);
+        for worker in 0..3u32 {
+            let (task_id, _handle) = runtime
+                .state
+                .create_task(root, Budget::INFINITE, async move {
+                    let cx = Cx::current().expect("lab task has a cx");
+                    let trace = TraceId::from_cx(&cx).to_hex();
+                    cx.log(
+                        LogEntry::info("worker started")
+                            .with_field("worker", worker.to_string())
+                            .with_field("trace_id", trace),
+                    );
+                })
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task_id, 0);
+        }
+        runtime.run_until_quiescent();
+        (runtime, capture)
+    }
+
+    #[test]
+    fn normalizer_numbers_ids_in_first_seen_order() {
+        init_test("normalizer_numbers_ids_in_first_seen_order");
+        let mut normalizer = IdNormalizer::new();
+        let text = normalizer.normalize("T7 spawned by T3 in R2; T7 done (S41)");
+        crate::assert_with_log!(
+            text == "task-1 spawned by task-2 in region-1; task-1 done (span-1)",
+            "first-seen numbering",
+            "task-1 spawned by task-2 in region-1; task-1 done (span-1)",
+            text
+        );
+        let trace = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let again = normalizer.normalize(&format!("trace={trace} T3"));
+        crate::assert_with_log!(
+            again == "trace=trace-1 task-2",
+            "mapping persists across calls",
+            "trace=trace-1 task-2",
+            again
+        );
+        let words = normalizer.normalize("Total Ready O");
+        crate::assert_with_log!(
+            words == "Total Ready O",
+            "plain words untouched",
+            "Total Ready O",
+            words
+        );
+        crate::test_complete!("normalizer_numbers_ids_in_first_seen_order");
+    }
+
+    #[test]
+    fn normalizer_accepts_user_rules() {
+        init_test("normalizer_accepts_user_rules");
+        let mut normalizer = IdNormalizer::new()
+            .with_rule(IdRule::prefixed("conn", "conn"))
+            .with_field("request", "req");
+        let text = normalizer.normalize("conn12 closed, conn4 open");
+        crate::assert_with_log!(
+            text == "conn-1 closed, conn-2 open",
+            "custom prefix rule",
+            "conn-1 closed, conn-2 open",
+            text
+        );
+        let field = normalizer.normalize_field("request", "GET /users/9");
+        crate::assert_with_log!(field == "req-1", "field rule", "req-1", field);
+        let other = normalizer.normalize_field("path", "/users/T9");
+        crate::assert_with_log!(
+            other == "/users/task-1",
+            "other fields use token rules",
+            "/users/task-1",
+            other
+        );
+        crate::test_complete!("normalizer_accepts_user_rules");
+    }
+
+    #[test]
+    fn log_capture_is_stable_across_same_seed_runs() {
+        init_test("log_capture_is_stable_across_same_seed_runs");
+        let (_first_runtime, first) = run_logging_lab(7);
+        let (_second_runtime, second) = run_logging_lab(7);
+
+        let count = first.entries().len();
+        crate::assert_with_log!(count == 3, "one entry per worker", 3, count);
+        let raw_first: Vec<String> = first
+            .entries()
+            .iter()
+            .map(LogEntry::format_compact)
+            .collect();
+        let raw_second: Vec<String> = second
+            .entries()
+            .iter()
+            .map(LogEntry::format_compact)
+            .collect();
+        crate::assert_with_log!(
+            raw_first != raw_second,
+            "raw span ids differ between runs",
+            "different",
+            (raw_first, raw_second)
+        );
+
+        let rendered = first.render();
+        let again = second.render();
+        crate::assert_with_log!(rendered == again, "normalized output", rendered, again);
+        crate::assert_with_log!(
+            rendered.starts_with(
+                "0ns INFO worker started region_id=region-1 span_id=span-1 task_id=task-1 trace_id=trace-1 worker="
+            ),
+            "canonical line format",
+            "0ns INFO worker started ...",
+            rendered
+        );
+        crate::test_complete!("log_capture_is_stable_across_same_seed_runs");
+    }
+
+    #[test]
+    fn render_quotes_and_escapes_values() {
+        init_test("render_quotes_and_escapes_values");
+        let capture = LogCapture::new().without_timestamps();
+        capture.collector().log(
+            LogEntry::warn("line one\nline two")
+                .with_field("path", "a b")
+                .with_field("empty", "")
+                .with_field("plain", "ok"),
+        );
+        let rendered = capture.render();
+        crate::assert_with_log!(
+            rendered == "WARN line one\\nline two empty=\"\" path=\"a b\" plain=ok\n",
+            "quoted rendering",
+            "WARN line one\\nline two empty=\"\" path=\"a b\" plain=ok",
+            rendered
+        );
+        crate::test_complete!("render_quotes_and_escapes_values");
+    }
+
+    #[test]
+    fn trace_summary_digests_events() {
+        init_test("trace_summary_digests_events");
+        let obligation = ObligationId::new_for_test(1, 1);
+        let events = vec![
+            TraceEvent::region_created(1, Time::ZERO, region(4), None),
+            TraceEvent::region_created(2, Time::ZERO, region(9), Some(region(4))),
+            TraceEvent::spawn(3, Time::ZERO, task(8), region(9)),
+            TraceEvent::obligation_reserve(
+                4,
+                Time::ZERO,
+                obligation,
+                task(8),
+                region(9),
+                ObligationKind::SendPermit,
+            ),
+            TraceEvent::spawn(5, Time::ZERO, task(2), region(4)),
+            TraceEvent::obligation_commit(
+                6,
+                Time::ZERO,
+                obligation,
+                task(8),
+                region(9),
+                ObligationKind::SendPermit,
+                10,
+            ),
+            TraceEvent::complete(7, Time::ZERO, task(8), region(9)),
+            TraceEvent::new(
+                8,
+                Time::ZERO,
+                TraceEventKind::RegionCloseBegin,
+                TraceData::Region {
+                    region: region(9),
+                    parent: Some(region(4)),
+                },
+            ),
+            TraceEvent::new(
+                9,
+                Time::ZERO,
+                TraceEventKind::RegionCloseComplete,
+                TraceData::Region {
+                    region: region(9),
+                    parent: Some(region(4)),
+                },
+            ),
+            TraceEvent::complete(10, Time::ZERO, task(2), region(4)),
+        ];
+        let summary = TraceSummary::from_events(&events);
+        let expected = "\
+events: 10
+  complete: 2
+  obligation_commit: 1
+  obligation_reserve: 1
+  region_close_begin: 1
+  region_close_complete: 1
+  region_created: 2
+  spawn: 2
+tasks:
+  task-1: complete=1 obligation_commit=1 obligation_reserve=1 spawn=1
+  task-2: complete=1 spawn=1
+regions:
+  region_created region-1
+  region_created region-2 parent=region-1
+  region_close_begin region-2 parent=region-1
+  region_close_complete region-2 parent=region-1
+obligations: reserved=1 committed=1 aborted=0 leaked=0
+";
+        let rendered = summary.to_string();
+        crate::assert_with_log!(rendered == expected, "summary text", expected, rendered);
+        crate::test_complete!("trace_summary_digests_events");
+    }
+
+    #[test]
+    fn trace_summary_is_stable_across_same_seed_runs() {
+        init_test("trace_summary_is_stable_across_same_seed_runs");
+        let (first, _) = run_logging_lab(11);
+        let (second, _) = run_logging_lab(11);
+        let a = TraceSummary::from_lab(&first);
+        let b = TraceSummary::from_lab(&second);
+        crate::assert_with_log!(a == b, "same-seed summaries", a.render(), b.render());
+        let spawns = a.event_counts.get("spawn").copied().unwrap_or(0);
+        crate::assert_with_log!(spawns == 3, "spawn count", 3, spawns);
+        crate::test_complete!("trace_summary_is_stable_across_same_seed_runs");
+    }
+
+    #[test]
+    fn diff_shows_changed_lines_with_context() {
+        init_test("diff_shows_changed_lines_with_context");
+        let expected = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+        let actual = "a\nb\nc\nD\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+        let diff = diff_lines(expected, actual);
+        let want = "\
+--- expected
++++ actual
+@@ -2,5 +2,5 @@
+ b
+ c
+-d
++D
+ e
+ f
+@@ -11,2 +11,3 @@
+ k
+ l
++m
+";
+        crate::assert_with_log!(diff == want, "unified diff", want, diff);
+        crate::test_complete!("diff_shows_changed_lines_with_context");
+    }
+
+    #[test]
+    fn snapshot_file_lifecycle_and_update_mode() {
+        init_test("snapshot_file_lifecycle_and_update_mode");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = snapshot_path(&dir.path().join("lifecycle.rs"), "fan out/logs");
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        crate::assert_with_log!(
+            file_name == "lifecycle__fan_out_logs.snap",
+            "sanitized file name",
+            "lifecycle__fan_out_logs.snap",
+            file_name
+        );
+
+        let created =
+            check_snapshot(&path, "fan out/logs", "one\ntwo", SnapshotMode::Check).expect("create");
+        crate::assert_with_log!(
+            created == SnapshotOutcome::Created,
+            "first run creates",
+            SnapshotOutcome::Created,
+            created
+        );
+        let stored = fs::read_to_string(&path).expect("read");
+        crate::assert_with_log!(
+            stored == "# asupersync snapshot: fan out/logs\none\ntwo\n",
+            "stored format",
+            "header + body",
+            stored
+        );
+
+        let matched = check_snapshot(&path, "fan out/logs", "one\ntwo\n", SnapshotMode::Check)
+            .expect("match");
+        crate::assert_with_log!(
+            matched == SnapshotOutcome::Matched,
+            "second run matches",
+            SnapshotOutcome::Matched,
+            matched
+        );
+
+        let err = check_snapshot(&path, "fan out/logs", "one\nthree\n", SnapshotMode::Check)
+            .expect_err("mismatch");
+        let message = err.to_string();
+        crate::assert_with_log!(
+            message.contains("-two\n+three") && message.contains(UPDATE_SNAPSHOTS_ENV),
+            "mismatch reports diff and update hint",
+            "diff + hint",
+            message
+        );
+        let untouched = fs::read_to_string(&path).expect("read");
+        crate::assert_with_log!(
+            untouched == stored,
+            "check mode keeps file",
+            stored,
+            untouched
+        );
+        let pending = fs::read_to_string(path.with_extension("snap.new")).expect("pending");
+        crate::assert_with_log!(
+            pending.ends_with("one\nthree\n"),
+            "pending output written",
+            "one\\nthree",
+            pending
+        );
+
+        let updated = check_snapshot(&path, "fan out/logs", "one\nthree\n", SnapshotMode::Update)
+            .expect("update");
+        crate::assert_with_log!(
+            updated == SnapshotOutcome::Updated,
+            "update mode rewrites",
+            SnapshotOutcome::Updated,
+            updated
+        );
+        let rematched = check_snapshot(&path, "fan out/logs", "one\nthree\n", SnapshotMode::Check)
+            .expect("rematch");
+        crate::assert_with_log!(
+            rematched == SnapshotOutcome::Matched,
+            "rewritten snapshot matches",
+            SnapshotOutcome::Matched,
+            rematched
+        );
+        crate::test_complete!("snapshot_file_lifecycle_and_update_mode");
+    }
+}
@@ -11,7 +11,7 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::task::Waker;
 use std::time::Duration;
 
-use super::wheel::{TimerWheel, WakerBatch};
+use super::wheel::{TimerClass, TimerMetrics, TimerReset, TimerWheel, WakerBatch};
 
 #[inline]
 fn duration_to_nanos_saturating(duration: Duration) -> u64 {
@@ -70,6 +70,25 @@ impl TimeSource for WallClock {
     }
 }
 
+/// Highest Unix-millisecond timestamp returned by [`unix_time_millis`].
+static UNIX_MILLIS_HIGH_WATER: AtomicU64 = AtomicU64::new(0);
+
+/// Returns non-decreasing Unix wall-clock milliseconds.
+///
+/// Used where an absolute, time-ordered timestamp is required (e.g. the
+/// UUIDv7-style ids minted by [`Cx::next_trace_id`](crate::cx::Cx::next_trace_id)).
+/// The result is clamped to a process-wide high-water mark so it never goes
+/// backwards when the system clock is stepped.
+pub(crate) fn unix_time_millis() -> u64 {
+    let wall_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+        });
+    let previous = UNIX_MILLIS_HIGH_WATER.fetch_max(wall_ms, Ordering::AcqRel);
+    previous.max(wall_ms)
+}
+
 /// Browser-oriented monotonic clock configuration.
 ///
 /// The browser clock adapter ingests host time samples (for example
@@ -404,6 +423,19 @@ impl TimeSource for VirtualClock {
 
 pub use super::wheel::TimerHandle;
 
+/// Outcome counts of a batched timer reset.
+///
+/// See [`TimerBatch::reset_many`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimerResetStats {
+    /// Timers whose deadline was rewritten in the wheel slot they occupy.
+    pub in_place: usize,
+    /// Timers taken out of the wheel and inserted again at the new deadline.
+    pub reinserted: usize,
+    /// Handles that were no longer active (already fired or cancelled).
+    pub inactive: usize,
+}
+
 /// Timer driver that manages timer registrations and fires them.
 ///
 /// The driver maintains a hierarchical timing wheel ordered by deadline.
@@ -465,6 +497,23 @@ impl<T: TimeSource> TimerDriver<T> {
         wheel.register(deadline, waker)
     }
 
+    /// Registers a timer in the given priority class.
+    ///
+    /// Timers sharing a deadline are dispatched by class first and then in
+    /// registration order; see [`TimerClass`].
+    pub fn register_with_class(
+        &self,
+        deadline: Time,
+        waker: Waker,
+        class: TimerClass,
+    ) -> TimerHandle {
+        let mut wheel = self.wheel.lock();
+        let now = self.clock.now();
+        wheel.synchronize(now);
+        crate::runtime::metrics::record_timer_registered();
+        wheel.register_with_class(deadline, waker, class)
+    }
+
     /// Updates an existing timer registration with a new deadline and waker.
     ///
     /// This doesn't actually remove the old entry from the heap (to avoid O(n)
@@ -495,6 +544,67 @@ impl<T: TimeSource> TimerDriver<T> {
         cancelled
     }
 
+    /// Registers every `(deadline, waker)` entry under a single lock
+    /// acquisition.
+    ///
+    /// Equivalent to calling [`register`](Self::register) once per entry in
+    /// order; handles are returned in entry order. The iterator runs while
+    /// the wheel lock is held, so it must not touch this driver.
+    pub fn register_many<I>(&self, entries: I) -> Vec<TimerHandle>
+    where
+        I: IntoIterator<Item = (Time, Waker)>,
+    {
+        let entries = entries.into_iter();
+        let mut handles = Vec::with_capacity(entries.size_hint().0);
+        let mut wheel = self.wheel.lock();
+        let now = self.clock.now();
+        wheel.synchronize(now);
+        for (deadline, waker) in entries {
+            crate::runtime::metrics::record_timer_registered();
+            handles.push(wheel.register(deadline, waker));
+        }
+        drop(wheel);
+        handles
+    }
+
+    /// Moves every active timer in `handles` to `deadline` under a single
+    /// lock acquisition, keeping handles and wakers.
+    ///
+    /// A timer whose new deadline maps to the wheel slot it already occupies
+    /// is updated in place; any other is reinserted. Both fire exactly when a
+    /// cancel followed by a fresh registration at `deadline` would.
+    pub fn reset_many(&self, handles: &[TimerHandle], deadline: Time) -> TimerResetStats {
+        let mut stats = TimerResetStats::default();
+        let mut wheel = self.wheel.lock();
+        let now = self.clock.now();
+        wheel.synchronize(now);
+        for handle in handles {
+            match wheel.reset(handle, deadline) {
+                Some(TimerReset::InPlace) => stats.in_place += 1,
+                Some(TimerReset::Reinserted) => stats.reinserted += 1,
+                None => stats.inactive += 1,
+            }
+        }
+        stats
+    }
+
+    /// Cancels every timer in `handles` under a single lock acquisition.
+    ///
+    /// Returns how many were active and are now cancelled.
+    pub fn cancel_many(&self, handles: &[TimerHandle]) -> usize {
+        let cancelled = {
+            let mut wheel = self.wheel.lock();
+            handles
+                .iter()
+                .filter(|handle| wheel.cancel(handle))
+                .count()
+        };
+        for _ in 0..cancelled {
+            crate::runtime::metrics::record_timer_cancelled();
+        }
+        cancelled
+    }
+
     /// Returns the next deadline that will fire, if any.
     #[must_use]
     pub fn next_deadline(&self) -> Option<Time> {
@@ -542,6 +652,13 @@ impl<T: TimeSource> TimerDriver<T> {
         self.wheel.lock().len()
     }
 
+    /// Returns the outstanding-timer counts, broken down by priority class.
+    #[inline]
+    #[must_use]
+    pub fn timer_metrics(&self) -> TimerMetrics {
+        self.wheel.lock().metrics()
+    }
+
     /// Returns true if there are no pending timers.
     #[inline]
     #[must_use]
@@ -586,12 +703,24 @@ pub trait TimerDriverApi: Send + Sync + std::fmt::Debug {
     /// Registers a timer to fire at the given deadline.
     fn register(&self, deadline: Time, waker: Waker) -> TimerHandle;
 
+    /// Registers a timer in the given priority class.
+    fn register_with_class(&self, deadline: Time, waker: Waker, class: TimerClass) -> TimerHandle;
+
     /// Updates an existing timer with a new deadline and waker.
     fn update(&self, handle: &TimerHandle, deadline: Time, waker: Waker) -> TimerHandle;
 
     /// Cancels an existing timer.
     fn cancel(&self, handle: &TimerHandle) -> bool;
 
+    /// Registers a batch of timers under one lock acquisition.
+    fn register_many(&self, entries: &mut dyn Iterator<Item = (Time, Waker)>) -> Vec<TimerHandle>;
+
+    /// Moves a batch of timers to a new deadline under one lock acquisition.
+    fn reset_many(&self, handles: &[TimerHandle], deadline: Time) -> TimerResetStats;
+
+    /// Cancels a batch of timers under one lock acquisition.
+    fn cancel_many(&self, handles: &[TimerHandle]) -> usize;
+
     /// Returns the next deadline that will fire.
     fn next_deadline(&self) -> Option<Time>;
 
@@ -601,6 +730,9 @@ pub trait TimerDriverApi: Send + Sync + std::fmt::Debug {
     /// Returns the number of pending timers.
     fn pending_count(&self) -> usize;
 
+    /// Returns the outstanding-timer counts by priority class.
+    fn timer_metrics(&self) -> TimerMetrics;
+
     /// Returns true if no timers are pending.
     fn is_empty(&self) -> bool;
 
@@ -621,6 +753,10 @@ impl<T: TimeSource + std::fmt::Debug + 'static> TimerDriverApi for TimerDriver<T
         Self::register(self, deadline, waker)
     }
 
+    fn register_with_class(&self, deadline: Time, waker: Waker, class: TimerClass) -> TimerHandle {
+        Self::register_with_class(self, deadline, waker, class)
+    }
+
     fn update(&self, handle: &TimerHandle, deadline: Time, waker: Waker) -> TimerHandle {
         Self::update(self, handle, deadline, waker)
     }
@@ -629,6 +765,18 @@ impl<T: TimeSource + std::fmt::Debug + 'static> TimerDriverApi for TimerDriver<T
         Self::cancel(self, handle)
     }
 
+    fn register_many(&self, entries: &mut dyn Iterator<Item = (Time, Waker)>) -> Vec<TimerHandle> {
+        Self::register_many(self, entries)
+    }
+
+    fn reset_many(&self, handles: &[TimerHandle], deadline: Time) -> TimerResetStats {
+        Self::reset_many(self, handles, deadline)
+    }
+
+    fn cancel_many(&self, handles: &[TimerHandle]) -> usize {
+        Self::cancel_many(self, handles)
+    }
+
     fn next_deadline(&self) -> Option<Time> {
         Self::next_deadline(self)
     }
@@ -641,6 +789,10 @@ impl<T: TimeSource + std::fmt::Debug + 'static> TimerDriverApi for TimerDriver<T
         Self::pending_count(self)
     }
 
+    fn timer_metrics(&self) -> TimerMetrics {
+        Self::timer_metrics(self)
+    }
+
     fn is_empty(&self) -> bool {
         Self::is_empty(self)
     }
@@ -731,6 +883,20 @@ impl TimerDriverHandle {
         self.inner.register(deadline, waker)
     }
 
+    /// Registers a timer in the given priority class.
+    ///
+    /// Among timers sharing a deadline, lower classes fire first.
+    #[inline]
+    #[must_use]
+    pub fn register_with_class(
+        &self,
+        deadline: Time,
+        waker: Waker,
+        class: TimerClass,
+    ) -> TimerHandle {
+        self.inner.register_with_class(deadline, waker, class)
+    }
+
     /// Updates an existing timer with a new deadline and waker.
     #[inline]
     #[must_use]
@@ -747,6 +913,13 @@ impl TimerDriverHandle {
         self.inner.cancel(handle)
     }
 
+    /// Returns a view for batched operations on this driver.
+    #[inline]
+    #[must_use]
+    pub const fn batch(&self) -> TimerBatch<'_> {
+        TimerBatch { timer: self }
+    }
+
     /// Returns the maximum timer duration before this driver clamps a
     /// registration to fire early at the horizon
     /// (br-asupersync-sleep-horizon-early-youlxs).
@@ -779,6 +952,13 @@ impl TimerDriverHandle {
         self.inner.pending_count()
     }
 
+    /// Returns the outstanding-timer counts by priority class.
+    #[inline]
+    #[must_use]
+    pub fn timer_metrics(&self) -> TimerMetrics {
+        self.inner.timer_metrics()
+    }
+
     /// Returns true if no timers are pending.
     #[inline]
     #[must_use]
@@ -787,6 +967,77 @@ impl TimerDriverHandle {
     }
 }
 
+/// Batched timer operations against one driver.
+///
+/// Servers register, reset, and cancel several timers per connection (idle,
+/// header-read, keep-alive). Each call here takes the driver's wheel lock
+/// once for the whole batch instead of once per timer, and
+/// [`reset_many`](Self::reset_many) moves a timer without the cancel and
+/// re-registration an [`update`](TimerDriverHandle::update) performs. Firing
+/// semantics are unchanged: a batch behaves exactly like the equivalent
+/// sequence of single-timer calls, under wall and virtual time alike.
+///
+/// # Example
+///
+/// ```
+/// use asupersync::time::{TimerDriverHandle, VirtualClock};
+/// use asupersync::types::Time;
+/// use std::sync::Arc;
+/// use std::task::Waker;
+///
+/// let timer = TimerDriverHandle::with_virtual_clock(Arc::new(VirtualClock::new()));
+/// let batch = timer.batch();
+/// let handles = batch.register_many([
+///     (Time::from_secs(30), Waker::noop().clone()),
+///     (Time::from_secs(60), Waker::noop().clone()),
+/// ]);
+///
+/// // Activity on the connection pushes the idle timeout out.
+/// let stats = batch.reset_many(&handles[..1], Time::from_secs(31));
+/// assert_eq!(stats.inactive, 0);
+///
+/// // Teardown cancels everything the connection still holds.
+/// assert_eq!(batch.cancel_many(&handles), 2);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TimerBatch<'a> {
+    timer: &'a TimerDriverHandle,
+}
+
+impl TimerBatch<'_> {
+    /// Registers every `(deadline, waker)` entry, returning handles in entry
+    /// order.
+    ///
+    /// The iterator runs while the wheel lock is held, so it must not touch
+    /// the timer driver.
+    #[must_use]
+    pub fn register_many<I>(self, entries: I) -> Vec<TimerHandle>
+    where
+        I: IntoIterator<Item = (Time, Waker)>,
+    {
+        self.timer.inner.register_many(&mut entries.into_iter())
+    }
+
+    /// Moves every active timer in `handles` to `deadline`, keeping handles
+    /// and wakers.
+    ///
+    /// A timer whose new deadline falls in the wheel slot it already occupies
+    /// is updated in place; any other is reinserted. Handles that already
+    /// fired or were cancelled are counted as inactive and left alone.
+    #[must_use]
+    pub fn reset_many(self, handles: &[TimerHandle], deadline: Time) -> TimerResetStats {
+        self.timer.inner.reset_many(handles, deadline)
+    }
+
+    /// Cancels every timer in `handles`.
+    ///
+    /// Returns how many were active and are now cancelled.
+    #[must_use]
+    pub fn cancel_many(self, handles: &[TimerHandle]) -> usize {
+        self.timer.inner.cancel_many(handles)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(
@@ -807,6 +1058,18 @@ mod tests {
         crate::test_phase!(name);
     }
 
+    #[test]
+    fn unix_time_millis_never_decreases() {
+        init_test("unix_time_millis_never_decreases");
+        let mut previous = unix_time_millis();
+        for _ in 0..1_000 {
+            let next = unix_time_millis();
+            crate::assert_with_log!(next >= previous, "non-decreasing", previous, next);
+            previous = next;
+        }
+        crate::test_complete!("unix_time_millis_never_decreases");
+    }
+
     // =========================================================================
     // VirtualClock Tests
     // =========================================================================
@@ -2588,4 +2851,137 @@ mod tests {
 
         crate::test_complete!("conformance_timer_driver_browser_clock_monotonic");
     }
+
+    #[test]
+    fn timer_batch_registers_resets_and_cancels_under_virtual_time() {
+        init_test("timer_batch_registers_resets_and_cancels_under_virtual_time");
+        let clock = Arc::new(VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(clock.clone());
+        let counters: Vec<_> = (0..3).map(|_| Arc::new(AtomicU64::new(0))).collect();
+
+        // Idle, header-read, and keep-alive timers for one connection.
+        let deadlines = [60, 10, 5].map(Time::from_secs);
+        let batch = timer.batch();
+        let handles = batch.register_many(
+            deadlines
+                .iter()
+                .zip(&counters)
+                .map(|(&deadline, counter)| (deadline, waker_that_increments(counter.clone()))),
+        );
+        crate::assert_with_log!(handles.len() == 3, "one handle per entry", 3, handles.len());
+        crate::assert_with_log!(
+            timer.pending_count() == 3,
+            "all registered",
+            3,
+            timer.pending_count()
+        );
+
+        // 60.1s stays in the idle timer's level-1 slot; 120s does not.
+        let stats = batch.reset_many(&handles[..1], Time::from_millis(60_100));
+        let expected = TimerResetStats {
+            in_place: 1,
+            reinserted: 0,
+            inactive: 0,
+        };
+        crate::assert_with_log!(stats == expected, "in-place reset", expected, stats);
+        let stats = batch.reset_many(&handles[..1], Time::from_secs(120));
+        let expected = TimerResetStats {
+            in_place: 0,
+            reinserted: 1,
+            inactive: 0,
+        };
+        crate::assert_with_log!(stats == expected, "fallback reset", expected, stats);
+
+        clock.advance_to(Time::from_secs(60));
+        let fired = timer.process_timers();
+        crate::assert_with_log!(fired == 2, "header and keep-alive fire", 2, fired);
+        let idle = counters[0].load(Ordering::SeqCst);
+        crate::assert_with_log!(idle == 0, "idle timer moved out", 0, idle);
+
+        clock.advance_to(Time::from_secs(120));
+        let fired = timer.process_timers();
+        crate::assert_with_log!(fired == 1, "idle fires at reset deadline", 1, fired);
+
+        // Fired handles are inactive; resetting them does nothing.
+        let stats = batch.reset_many(&handles, Time::from_secs(200));
+        crate::assert_with_log!(stats.inactive == 3, "all inactive", 3, stats.inactive);
+        crate::assert_with_log!(timer.is_empty(), "nothing pending", true, timer.is_empty());
+        crate::test_complete!("timer_batch_registers_resets_and_cancels_under_virtual_time");
+    }
+
+    #[test]
+    fn timer_batch_cancels_connection_timers_on_teardown() {
+        init_test("timer_batch_cancels_connection_timers_on_teardown");
+        let clock = Arc::new(VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(clock.clone());
+        let counter = Arc::new(AtomicU64::new(0));
+        let waker = waker_that_increments(counter.clone());
+        let batch = timer.batch();
+        let entries = (1..=3).map(|secs| (Time::from_secs(secs), waker.clone()));
+        let handles = batch.register_many(entries);
+
+        let cancelled = batch.cancel_many(&handles);
+        crate::assert_with_log!(cancelled == 3, "all cancelled", 3, cancelled);
+        let again = batch.cancel_many(&handles);
+        crate::assert_with_log!(again == 0, "second cancel is a no-op", 0, again);
+
+        clock.advance_to(Time::from_secs(10));
+        let fired = timer.process_timers();
+        crate::assert_with_log!(fired == 0, "cancelled timers never fire", 0, fired);
+        let wakes = counter.load(Ordering::SeqCst);
+        crate::assert_with_log!(wakes == 0, "no wakes", 0, wakes);
+        crate::test_complete!("timer_batch_cancels_connection_timers_on_teardown");
+    }
+    /// A waker that appends its tag to a shared log when woken.
+    struct OrderWaker {
+        tag: usize,
+        log: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Wake for OrderWaker {
+        fn wake(self: Arc<Self>) {
+            self.log.lock().push(self.tag);
+        }
+    }
+
+    #[test]
+    fn burst_expiry_dispatches_by_class_then_registration_order() {
+        init_test("burst_expiry_dispatches_by_class_then_registration_order");
+        let clock = Arc::new(VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(clock.clone());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let deadline = Time::from_millis(250);
+
+        let mut expected: Vec<(TimerClass, usize)> = Vec::new();
+        for tag in 0..1_000 {
+            let class = TimerClass::ALL[(tag * 7 + tag / 3) % TimerClass::COUNT];
+            let waker = Arc::new(OrderWaker {
+                tag,
+                log: log.clone(),
+            })
+            .into();
+            let _ = timer.register_with_class(deadline, waker, class);
+            expected.push((class, tag));
+        }
+        expected.sort();
+        let expected: Vec<usize> = expected.into_iter().map(|(_, tag)| tag).collect();
+
+        let metrics = timer.timer_metrics();
+        crate::assert_with_log!(metrics.outstanding == 1_000, "outstanding", 1_000, metrics);
+        let per_class: usize = TimerClass::ALL.iter().map(|c| metrics.class(*c)).sum();
+        crate::assert_with_log!(per_class == 1_000, "class breakdown sums", 1_000, per_class);
+
+        clock.advance_to(Time::from_millis(249));
+        let early = timer.process_timers();
+        crate::assert_with_log!(early == 0, "nothing before deadline", 0, early);
+
+        clock.advance_to(deadline);
+        let fired = timer.process_timers();
+        crate::assert_with_log!(fired == 1_000, "whole burst fires", 1_000, fired);
+        let order = log.lock().clone();
+        crate::assert_with_log!(order == expected, "class then FIFO", true, order == expected);
+        let metrics = timer.timer_metrics();
+        crate::assert_with_log!(metrics.outstanding == 0, "drained", 0, metrics);
+        crate::test_complete!("burst_expiry_dispatches_by_class_then_registration_order");
+    }
 }
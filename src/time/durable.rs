@@ -0,0 +1,935 @@
+//! Durable timers that survive process restart.
+//!
+//! [`Sleep`] lives in memory: a restart forgets every pending deadline. Work
+//! scheduled hours or days ahead (subscription renewals, lease expirations)
+//! needs the deadline written down first. [`DurableTimers`] persists each
+//! timer to a [`TimerStore`] before handing back a [`DurableSleep`] future,
+//! which fires like a normal timer while the process lives. After a restart,
+//! [`DurableTimers::recover`] replays what the previous process left behind
+//! to a handler: overdue entries at once, marked late by how far they are
+//! overdue, pending ones at their deadline.
+//!
+//! # Delivery Guarantee
+//!
+//! An entry stays in the store until it is acknowledged with
+//! [`DurableTimers::ack`] (recovery acks after the handler succeeds). Once
+//! acked, the entry never fires again, in this process or after a restart.
+//! A crash between firing and the ack redelivers the entry on the next
+//! recovery, so delivery is at-least-once; handlers deduplicate with
+//! [`DurableFire::idempotency_key`], which is stable across restarts.
+//!
+//! # Keys
+//!
+//! A key has at most one deadline. Scheduling a key that is already
+//! scheduled fails with [`DurableTimerError::KeyExists`] by default; under
+//! [`KeyCollision::Reschedule`] the new deadline and payload replace the old
+//! ones and the old [`DurableSleep`] resolves to
+//! [`DurableTimerError::Superseded`].
+//!
+//! # Clocks
+//!
+//! Deadlines are persistent time: by default milliseconds since the Unix
+//! epoch, which the runtime's [`Time`] (process- or lab-relative) is not.
+//! Each [`DurableSleep`] converts its remaining time to a runtime [`Sleep`],
+//! so live timers keep the runtime's precision, virtual time included.
+//! [`DurableTimers::with_clock`] substitutes the persistent clock; lab tests
+//! pass a [`VirtualClock`](super::VirtualClock) and simulate a restart by
+//! crashing a [`SimFs`](crate::lab::sim_fs::SimFs) under a
+//! [`LogTimerStore`] and reopening it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::fs::UnixVfs;
+//! use asupersync::time::{DurableTimers, LogTimerStore};
+//!
+//! let store = LogTimerStore::new(UnixVfs::new(), "/var/lib/billing/timers.log");
+//! let timers = DurableTimers::open(store).await?;
+//!
+//! // Replay what the previous process left behind.
+//! let report = timers
+//!     .recover(&cx, |fire| async move { renew(&fire.payload, fire.idempotency_key()).await })
+//!     .await?;
+//!
+//! let deadline = timers.now() + Duration::from_secs(30 * 86_400);
+//! let renewal = timers.durable_sleep_until(&cx, "renew:42", deadline, payload).await?;
+//! let fire = renewal.await?;
+//! renew(&fire.payload, fire.idempotency_key()).await?;
+//! timers.ack(&cx, &fire).await?;
+//! ```
+
+use super::{Sleep, TimeSource, unix_time_millis, wall_now};
+use crate::cx::Cx;
+use crate::fs::{OpenOptions, Vfs, VfsFile};
+use crate::io::AsyncWriteExt;
+use crate::sync::LockError;
+use crate::types::Time;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Log records a [`LogTimerStore`] tolerates before compacting by default.
+const DEFAULT_COMPACTION_THRESHOLD: usize = 1024;
+
+/// A persisted timer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerEntry {
+    /// Key the timer is scheduled under.
+    pub key: String,
+    /// Deadline, in persistent time.
+    pub deadline: Time,
+    /// Opaque data handed back when the timer fires.
+    pub payload: Vec<u8>,
+}
+
+/// Storage behind [`DurableTimers`].
+///
+/// Every method must be durable when it returns: an entry `put` before a
+/// crash is loaded after it, and an entry removed before a crash is not.
+pub trait TimerStore: Send {
+    /// Loads every entry that was put and not removed.
+    fn load(&mut self) -> impl Future<Output = io::Result<Vec<TimerEntry>>> + Send;
+
+    /// Records `entry`, replacing any entry with the same key.
+    fn put(&mut self, entry: &TimerEntry) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Removes the entry for `key` if its deadline is `deadline`.
+    fn remove(&mut self, key: &str, deadline: Time) -> impl Future<Output = io::Result<()>> + Send;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogRecord {
+    Put(TimerEntry),
+    Remove { key: String, deadline: Time },
+}
+
+/// A [`TimerStore`] that appends one JSON line per change to a file on a
+/// [`Vfs`], syncing each before returning.
+///
+/// The log is rewritten from the live entries (write a temporary file,
+/// sync, rename) once it holds more than the compaction threshold or twice
+/// the live entries, whichever is larger, so its size stays proportional to
+/// what is scheduled. A record torn by a crash mid-append was never
+/// acknowledged; loading drops it and compacts.
+pub struct LogTimerStore<V: Vfs> {
+    vfs: V,
+    path: PathBuf,
+    file: Option<V::File>,
+    live: BTreeMap<String, TimerEntry>,
+    records: usize,
+    compaction_threshold: usize,
+}
+
+impl<V: Vfs> LogTimerStore<V> {
+    /// Creates a store logging to `path`. Nothing is touched until
+    /// [`TimerStore::load`].
+    #[must_use]
+    pub fn new(vfs: V, path: impl Into<PathBuf>) -> Self {
+        Self {
+            vfs,
+            path: path.into(),
+            file: None,
+            live: BTreeMap::new(),
+            records: 0,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD,
+        }
+    }
+
+    /// Sets the number of log records tolerated before compacting (default
+    /// 1024, minimum 1).
+    #[must_use]
+    pub fn with_compaction_threshold(mut self, records: usize) -> Self {
+        self.compaction_threshold = records.max(1);
+        self
+    }
+
+    /// Returns the number of records in the log.
+    #[must_use]
+    pub const fn log_records(&self) -> usize {
+        self.records
+    }
+
+    fn apply(&mut self, record: LogRecord) {
+        match record {
+            LogRecord::Put(entry) => {
+                self.live.insert(entry.key.clone(), entry);
+            }
+            LogRecord::Remove { key, deadline } => {
+                if self.live.get(&key).is_some_and(|entry| entry.deadline == deadline) {
+                    self.live.remove(&key);
+                }
+            }
+        }
+    }
+
+    async fn open_log(&self) -> io::Result<V::File> {
+        let opts = OpenOptions::new().append(true).create(true);
+        self.vfs.open(&self.path, &opts).await
+    }
+
+    async fn append(&mut self, record: LogRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&record).map_err(io::Error::other)?;
+        line.push(b'\n');
+        if self.file.is_none() {
+            self.file = Some(self.open_log().await?);
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(&line).await?;
+            file.sync_data().await?;
+        }
+        self.records += 1;
+        self.apply(record);
+        if self.records > self.compaction_threshold.max(2 * self.live.len()) {
+            self.compact().await?;
+        }
+        Ok(())
+    }
+
+    async fn compact(&mut self) -> io::Result<()> {
+        let mut contents = Vec::new();
+        for entry in self.live.values() {
+            let record = LogRecord::Put(entry.clone());
+            serde_json::to_writer(&mut contents, &record).map_err(io::Error::other)?;
+            contents.push(b'\n');
+        }
+        let mut staging = self.path.clone().into_os_string();
+        staging.push(".compact");
+        let staging = PathBuf::from(staging);
+        let opts = OpenOptions::new().write(true).create(true).truncate(true);
+        let mut file = self.vfs.open(&staging, &opts).await?;
+        file.write_all(&contents).await?;
+        file.sync_all().await?;
+        drop(file);
+        self.vfs.rename(&staging, &self.path).await?;
+        self.records = self.live.len();
+        self.file = Some(self.open_log().await?);
+        Ok(())
+    }
+}
+
+impl<V: Vfs> fmt::Debug for LogTimerStore<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogTimerStore")
+            .field("path", &self.path)
+            .field("live", &self.live.len())
+            .field("records", &self.records)
+            .field("compaction_threshold", &self.compaction_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V: Vfs> TimerStore for LogTimerStore<V> {
+    async fn load(&mut self) -> io::Result<Vec<TimerEntry>> {
+        let contents = match self.vfs.read(&self.path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        self.live.clear();
+        self.records = 0;
+        let mut torn = false;
+        for (index, line) in contents.split_inclusive(|&byte| byte == b'\n').enumerate() {
+            let Some(line) = line.strip_suffix(b"\n") else {
+                torn = true;
+                break;
+            };
+            let record = serde_json::from_slice(line).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("timer log line {}: {err}", index + 1),
+                )
+            })?;
+            self.records += 1;
+            self.apply(record);
+        }
+        if torn || self.records > self.compaction_threshold.max(2 * self.live.len()) {
+            self.compact().await?;
+        } else {
+            self.file = Some(self.open_log().await?);
+        }
+        Ok(self.live.values().cloned().collect())
+    }
+
+    async fn put(&mut self, entry: &TimerEntry) -> io::Result<()> {
+        self.append(LogRecord::Put(entry.clone())).await
+    }
+
+    async fn remove(&mut self, key: &str, deadline: Time) -> io::Result<()> {
+        let scheduled = self.live.get(key).is_some_and(|entry| entry.deadline == deadline);
+        if !scheduled {
+            return Ok(());
+        }
+        let key = key.to_owned();
+        self.append(LogRecord::Remove { key, deadline }).await
+    }
+}
+
+/// What to do when a key that is already scheduled is scheduled again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyCollision {
+    /// Fail with [`DurableTimerError::KeyExists`].
+    #[default]
+    Reject,
+    /// Replace the scheduled deadline and payload.
+    Reschedule,
+}
+
+/// Errors from [`DurableTimers`].
+#[derive(Debug, thiserror::Error)]
+pub enum DurableTimerError {
+    /// The key is already scheduled and collisions are rejected.
+    #[error("durable timer {key:?} is already scheduled for {deadline:?}")]
+    KeyExists {
+        /// The key.
+        key: String,
+        /// Its scheduled deadline.
+        deadline: Time,
+    },
+    /// The timer was rescheduled, cancelled, or acknowledged before it
+    /// fired.
+    #[error("durable timer {key:?} was superseded before it fired")]
+    Superseded {
+        /// The key.
+        key: String,
+    },
+    /// The store failed; the operation did not take effect.
+    #[error("timer store failed: {0}")]
+    Store(#[from] io::Error),
+    /// Waiting for the store was cancelled or failed.
+    #[error("timer store lock failed: {0}")]
+    Lock(#[from] LockError),
+    /// A [`DurableSleep`] was polled after it completed.
+    #[error("durable sleep polled after completion")]
+    PolledAfterCompletion,
+}
+
+/// A fired durable timer. Ack it with [`DurableTimers::ack`] once handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurableFire {
+    /// Key the timer was scheduled under.
+    pub key: String,
+    /// Deadline, in persistent time.
+    pub deadline: Time,
+    /// Data given when scheduling.
+    pub payload: Vec<u8>,
+    /// How far past its deadline recovery found the entry; `None` for a
+    /// timer that fired on time.
+    pub late_by: Option<Duration>,
+}
+
+impl DurableFire {
+    fn new(entry: TimerEntry, late_by: Option<Duration>) -> Self {
+        Self {
+            key: entry.key,
+            deadline: entry.deadline,
+            payload: entry.payload,
+            late_by,
+        }
+    }
+
+    /// Returns a key identifying this firing across restarts and
+    /// redeliveries: the same key and deadline give the same value.
+    #[must_use]
+    pub fn idempotency_key(&self) -> String {
+        format!("{}@{}", self.key, self.deadline.as_nanos())
+    }
+
+    /// Returns true if recovery delivered this firing after its deadline.
+    #[must_use]
+    pub const fn is_late(&self) -> bool {
+        self.late_by.is_some()
+    }
+}
+
+/// Outcome of [`DurableTimers::recover`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Entries handled and acknowledged.
+    pub delivered: usize,
+    /// Of those, entries that were overdue.
+    pub late: usize,
+    /// Entries whose handler failed; they stay stored for the next recovery.
+    pub failed: usize,
+    /// Entries rescheduled, cancelled, or acknowledged before their turn.
+    pub superseded: usize,
+}
+
+/// Scheduled keys, mirroring the store. Each scheduling gets a fresh
+/// generation so stale futures can tell they were superseded.
+#[derive(Debug, Default)]
+struct Schedule {
+    entries: BTreeMap<String, (Time, u64)>,
+    recovered: Vec<(TimerEntry, u64)>,
+    next_generation: u64,
+}
+
+impl Schedule {
+    fn insert(&mut self, key: &str, deadline: Time) -> u64 {
+        self.next_generation += 1;
+        let generation = self.next_generation;
+        self.entries.insert(key.to_owned(), (deadline, generation));
+        generation
+    }
+
+    fn deadline(&self, key: &str) -> Option<Time> {
+        self.entries.get(key).map(|&(deadline, _)| deadline)
+    }
+
+    fn is_current(&self, key: &str, generation: u64) -> bool {
+        self.entries
+            .get(key)
+            .is_some_and(|&(_, current)| current == generation)
+    }
+}
+
+/// Persistent time as milliseconds since the Unix epoch, never decreasing.
+#[derive(Debug)]
+struct UnixClock;
+
+impl TimeSource for UnixClock {
+    fn now(&self) -> Time {
+        Time::from_millis(unix_time_millis())
+    }
+}
+
+/// Timers persisted to a [`TimerStore`]. See the [module docs](self).
+pub struct DurableTimers<S> {
+    store: crate::sync::Mutex<S>,
+    schedule: Arc<Mutex<Schedule>>,
+    clock: Arc<dyn TimeSource>,
+    collision: KeyCollision,
+}
+
+impl<S> fmt::Debug for DurableTimers<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schedule = self.schedule.lock();
+        f.debug_struct("DurableTimers")
+            .field("scheduled", &schedule.entries.len())
+            .field("awaiting_recovery", &schedule.recovered.len())
+            .field("collision", &self.collision)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: TimerStore> DurableTimers<S> {
+    /// Loads `store`. Entries found there are scheduled and wait for
+    /// [`Self::recover`].
+    pub async fn open(mut store: S) -> Result<Self, DurableTimerError> {
+        let mut schedule = Schedule::default();
+        for entry in store.load().await? {
+            let generation = schedule.insert(&entry.key, entry.deadline);
+            schedule.recovered.push((entry, generation));
+        }
+        Ok(Self {
+            store: crate::sync::Mutex::new(store),
+            schedule: Arc::new(Mutex::new(schedule)),
+            clock: Arc::new(UnixClock),
+            collision: KeyCollision::default(),
+        })
+    }
+
+    /// Replaces the persistent clock deadlines are measured on.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets what scheduling an already scheduled key does (default
+    /// [`KeyCollision::Reject`]).
+    #[must_use]
+    pub const fn with_key_collision(mut self, collision: KeyCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// Returns the current persistent time.
+    #[must_use]
+    pub fn now(&self) -> Time {
+        self.clock.now()
+    }
+
+    /// Returns the deadline `key` is scheduled for.
+    #[must_use]
+    pub fn scheduled(&self, key: &str) -> Option<Time> {
+        self.schedule.lock().deadline(key)
+    }
+
+    /// Persists a timer for `key` at `deadline`, then returns a future that
+    /// fires at the deadline. Nothing is scheduled if this fails.
+    pub async fn durable_sleep_until(
+        &self,
+        cx: &Cx,
+        key: &str,
+        deadline: Time,
+        payload: Vec<u8>,
+    ) -> Result<DurableSleep, DurableTimerError> {
+        let mut store = self.store.lock(cx).await?;
+        let existing = self.schedule.lock().deadline(key);
+        if let (Some(existing), KeyCollision::Reject) = (existing, self.collision) {
+            return Err(DurableTimerError::KeyExists {
+                key: key.to_owned(),
+                deadline: existing,
+            });
+        }
+        let entry = TimerEntry {
+            key: key.to_owned(),
+            deadline,
+            payload,
+        };
+        store.put(&entry).await?;
+        let generation = self.schedule.lock().insert(&entry.key, deadline);
+        drop(store);
+        let remaining = Duration::from_nanos(deadline.duration_since(self.clock.now()));
+        Ok(DurableSleep {
+            sleep: Sleep::after(wall_now(), remaining),
+            entry: Some(entry),
+            generation,
+            schedule: Arc::clone(&self.schedule),
+        })
+    }
+
+    /// Removes a fired timer from the store. Returns false if it was
+    /// already acknowledged, cancelled, or rescheduled to another deadline.
+    pub async fn ack(&self, cx: &Cx, fire: &DurableFire) -> Result<bool, DurableTimerError> {
+        self.unschedule(cx, &fire.key, Some(fire.deadline)).await
+    }
+
+    /// Removes the timer scheduled for `key`, if any. Its [`DurableSleep`]
+    /// resolves to [`DurableTimerError::Superseded`].
+    pub async fn cancel(&self, cx: &Cx, key: &str) -> Result<bool, DurableTimerError> {
+        self.unschedule(cx, key, None).await
+    }
+
+    async fn unschedule(
+        &self,
+        cx: &Cx,
+        key: &str,
+        deadline: Option<Time>,
+    ) -> Result<bool, DurableTimerError> {
+        let mut store = self.store.lock(cx).await?;
+        let scheduled = self.schedule.lock().deadline(key);
+        let Some(scheduled) = scheduled.filter(|&at| deadline.is_none_or(|d| d == at)) else {
+            return Ok(false);
+        };
+        store.remove(key, scheduled).await?;
+        self.schedule.lock().entries.remove(key);
+        drop(store);
+        Ok(true)
+    }
+
+    /// Delivers the entries found by [`Self::open`] to `handler` in
+    /// deadline order, acknowledging each one the handler accepts.
+    ///
+    /// Overdue entries are delivered at once with [`DurableFire::late_by`]
+    /// set; pending ones at their deadline, so the future runs until the
+    /// last of them fires. An entry whose handler fails stays stored and is
+    /// redelivered by the next recovery. Later calls deliver nothing.
+    pub async fn recover<F, Fut, E>(
+        &self,
+        cx: &Cx,
+        mut handler: F,
+    ) -> Result<RecoveryReport, DurableTimerError>
+    where
+        F: FnMut(DurableFire) -> Fut + Send,
+        Fut: Future<Output = Result<(), E>> + Send,
+    {
+        let mut recovered = std::mem::take(&mut self.schedule.lock().recovered);
+        recovered.sort_by_key(|(entry, _)| entry.deadline);
+        let mut report = RecoveryReport::default();
+        for (entry, generation) in recovered {
+            let now = self.clock.now();
+            let late_by = if entry.deadline <= now {
+                Some(Duration::from_nanos(now.duration_since(entry.deadline)))
+            } else {
+                let remaining = Duration::from_nanos(entry.deadline.duration_since(now));
+                Sleep::after(wall_now(), remaining).await;
+                None
+            };
+            if !self.schedule.lock().is_current(&entry.key, generation) {
+                report.superseded += 1;
+                continue;
+            }
+            let fire = DurableFire::new(entry, late_by);
+            if handler(fire.clone()).await.is_err() {
+                report.failed += 1;
+                continue;
+            }
+            self.ack(cx, &fire).await?;
+            report.delivered += 1;
+            report.late += usize::from(fire.is_late());
+        }
+        Ok(report)
+    }
+}
+
+/// Future returned by [`DurableTimers::durable_sleep_until`].
+///
+/// Resolves at the deadline to the [`DurableFire`], or to
+/// [`DurableTimerError::Superseded`] if the timer was rescheduled,
+/// cancelled, or acknowledged meanwhile. Dropping it leaves the entry
+/// stored; the next process recovers it.
+#[derive(Debug)]
+pub struct DurableSleep {
+    sleep: Sleep,
+    entry: Option<TimerEntry>,
+    generation: u64,
+    schedule: Arc<Mutex<Schedule>>,
+}
+
+impl DurableSleep {
+    /// Returns the runtime deadline the sleep fires at.
+    #[must_use]
+    pub const fn runtime_deadline(&self) -> Time {
+        self.sleep.deadline()
+    }
+}
+
+impl Future for DurableSleep {
+    type Output = Result<DurableFire, DurableTimerError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.entry.is_none() {
+            return Poll::Ready(Err(DurableTimerError::PolledAfterCompletion));
+        }
+        if Pin::new(&mut self.sleep).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let Some(entry) = self.entry.take() else {
+            return Poll::Ready(Err(DurableTimerError::PolledAfterCompletion));
+        };
+        let current = self.schedule.lock().is_current(&entry.key, self.generation);
+        Poll::Ready(if current {
+            Ok(DurableFire::new(entry, None))
+        } else {
+            Err(DurableTimerError::Superseded { key: entry.key })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+
+    use super::*;
+    use crate::lab::sim_fs::{SimFs, SimFsFault};
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::time::VirtualClock;
+    use crate::types::Budget;
+    use futures_lite::future::block_on;
+    use std::path::Path;
+
+    const LOG: &str = "/timers.log";
+    const EPOCH: Time = Time::from_secs(1_700_000_000);
+    const HOUR: Duration = Duration::from_secs(3_600);
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    async fn open(fs: &SimFs, clock: &Arc<VirtualClock>) -> DurableTimers<LogTimerStore<SimFs>> {
+        DurableTimers::open(LogTimerStore::new(fs.clone(), LOG))
+            .await
+            .expect("open timers")
+            .with_clock(clock.clone())
+    }
+
+    fn log_lines(fs: &SimFs) -> usize {
+        fs.durable_contents(Path::new(LOG))
+            .map_or(0, |contents| contents.iter().filter(|&&byte| byte == b'\n').count())
+    }
+
+    #[test]
+    fn overdue_entries_fire_once_after_restart() {
+        init_test("overdue_entries_fire_once_after_restart");
+        let fs = SimFs::default();
+        let clock = Arc::new(VirtualClock::starting_at(EPOCH));
+        let cx = Cx::for_testing();
+        block_on(async {
+            let timers = open(&fs, &clock).await;
+            for (key, hours) in [("lease:7", 2), ("renew:1", 1)] {
+                let payload = key.as_bytes().to_vec();
+                let sleep = timers
+                    .durable_sleep_until(&cx, key, EPOCH + HOUR * hours, payload)
+                    .await
+                    .expect("schedule");
+                // The process dies before either sleep fires.
+                drop(sleep);
+            }
+            drop(timers);
+            fs.inject(SimFsFault::Crash);
+            clock.set(EPOCH + HOUR * 3);
+
+            let timers = open(&fs, &clock).await;
+            let mut fired = Vec::new();
+            let report = timers
+                .recover(&cx, |fire| {
+                    fired.push(fire);
+                    async { Ok::<_, ()>(()) }
+                })
+                .await
+                .expect("recover");
+            let expected = RecoveryReport {
+                delivered: 2,
+                late: 2,
+                failed: 0,
+                superseded: 0,
+            };
+            crate::assert_with_log!(report == expected, "report", expected, report);
+            let lateness: Vec<_> = fired
+                .iter()
+                .map(|fire| (fire.key.as_str(), fire.late_by))
+                .collect();
+            let expected = vec![("renew:1", Some(HOUR * 2)), ("lease:7", Some(HOUR))];
+            crate::assert_with_log!(lateness == expected, "deadline order", expected, lateness);
+            crate::assert_with_log!(
+                fired[0].payload == b"renew:1",
+                "payload",
+                "renew:1",
+                fired[0].payload
+            );
+
+            drop(timers);
+            fs.inject(SimFsFault::Crash);
+            let timers = open(&fs, &clock).await;
+            let report = timers
+                .recover(&cx, |fire| async move { Err::<(), _>(fire) })
+                .await
+                .expect("recover");
+            let nothing = report == RecoveryReport::default();
+            crate::assert_with_log!(nothing, "acked entries stay gone", "empty", report);
+        });
+        crate::test_complete!("overdue_entries_fire_once_after_restart");
+    }
+
+    #[test]
+    fn crash_between_fire_and_ack_redelivers() {
+        init_test("crash_between_fire_and_ack_redelivers");
+        let fs = SimFs::default();
+        let clock = Arc::new(VirtualClock::starting_at(EPOCH));
+        let cx = Cx::for_testing();
+        block_on(async {
+            let timers = open(&fs, &clock).await;
+            let payload = b"invoice-9".to_vec();
+            let sleep = timers
+                .durable_sleep_until(&cx, "renew:9", EPOCH + HOUR, payload)
+                .await
+                .expect("schedule");
+            drop(sleep);
+            drop(timers);
+            fs.inject(SimFsFault::Crash);
+            clock.set(EPOCH + HOUR * 2);
+
+            let mut deliveries = Vec::new();
+            let timers = open(&fs, &clock).await;
+            let result = timers
+                .recover(&cx, |fire| {
+                    deliveries.push(fire.idempotency_key());
+                    // The side effect happened; the process dies before the
+                    // ack is durable.
+                    fs.inject(SimFsFault::Crash);
+                    async { Ok::<_, ()>(()) }
+                })
+                .await;
+            let lost_ack = matches!(result, Err(DurableTimerError::Store(_)));
+            crate::assert_with_log!(lost_ack, "ack fails with the process", "Store", result);
+            drop(timers);
+
+            for _ in 0..2 {
+                let timers = open(&fs, &clock).await;
+                timers
+                    .recover(&cx, |fire| {
+                        deliveries.push(fire.idempotency_key());
+                        async { Ok::<_, ()>(()) }
+                    })
+                    .await
+                    .expect("recover");
+                drop(timers);
+                fs.inject(SimFsFault::Crash);
+            }
+            let key = format!("renew:9@{}", (EPOCH + HOUR).as_nanos());
+            let expected = vec![key.clone(), key];
+            crate::assert_with_log!(
+                deliveries == expected,
+                "redelivered once, same idempotency key",
+                expected,
+                deliveries
+            );
+        });
+        crate::test_complete!("crash_between_fire_and_ack_redelivers");
+    }
+
+    #[test]
+    fn compaction_keeps_the_log_bounded() {
+        init_test("compaction_keeps_the_log_bounded");
+        let fs = SimFs::default();
+        let clock = Arc::new(VirtualClock::starting_at(EPOCH));
+        let cx = Cx::for_testing();
+        block_on(async {
+            let store = LogTimerStore::new(fs.clone(), LOG).with_compaction_threshold(16);
+            let timers = DurableTimers::open(store)
+                .await
+                .expect("open timers")
+                .with_clock(clock.clone());
+            for key in ["keep:a", "keep:b"] {
+                let sleep = timers
+                    .durable_sleep_until(&cx, key, EPOCH + HOUR * 24, Vec::new())
+                    .await
+                    .expect("schedule");
+                drop(sleep);
+            }
+            let mut peak = 0;
+            for round in 0..500 {
+                let key = format!("job:{round}");
+                let sleep = timers
+                    .durable_sleep_until(&cx, &key, EPOCH + HOUR, vec![0; 64])
+                    .await
+                    .expect("schedule");
+                drop(sleep);
+                let cancelled = timers.cancel(&cx, &key).await.expect("cancel");
+                assert!(cancelled);
+                peak = peak.max(log_lines(&fs));
+            }
+            crate::assert_with_log!(peak <= 16, "log bounded", "<= 16", peak);
+
+            drop(timers);
+            fs.inject(SimFsFault::Crash);
+            let timers = open(&fs, &clock).await;
+            let kept = timers.scheduled("keep:a").is_some() && timers.scheduled("keep:b").is_some();
+            crate::assert_with_log!(kept, "live entries survive", true, kept);
+            let dropped = timers.scheduled("job:0");
+            crate::assert_with_log!(dropped.is_none(), "cancelled gone", "None", dropped);
+        });
+        crate::test_complete!("compaction_keeps_the_log_bounded");
+    }
+
+    #[test]
+    fn key_collision_rejects_or_reschedules() {
+        init_test("key_collision_rejects_or_reschedules");
+        let fs = SimFs::default();
+        // Both deadlines are already due, so the sleeps fire on first poll.
+        let clock = Arc::new(VirtualClock::starting_at(EPOCH + HOUR * 3));
+        let cx = Cx::for_testing();
+        block_on(async {
+            let timers = open(&fs, &clock).await;
+            let first = timers
+                .durable_sleep_until(&cx, "lease:1", EPOCH + HOUR, b"v1".to_vec())
+                .await
+                .expect("schedule");
+            let result = timers
+                .durable_sleep_until(&cx, "lease:1", EPOCH + HOUR * 2, b"v2".to_vec())
+                .await;
+            let rejected = matches!(
+                &result,
+                Err(DurableTimerError::KeyExists { deadline, .. }) if *deadline == EPOCH + HOUR
+            );
+            crate::assert_with_log!(rejected, "rejected by default", "KeyExists", result);
+            let kept = timers.scheduled("lease:1");
+            crate::assert_with_log!(kept == Some(EPOCH + HOUR), "unchanged", EPOCH + HOUR, kept);
+
+            let timers = timers.with_key_collision(KeyCollision::Reschedule);
+            let second = timers
+                .durable_sleep_until(&cx, "lease:1", EPOCH + HOUR * 2, b"v2".to_vec())
+                .await
+                .expect("reschedule");
+            let moved = timers.scheduled("lease:1");
+            let expected = Some(EPOCH + HOUR * 2);
+            crate::assert_with_log!(moved == expected, "rescheduled", expected, moved);
+
+            let stale = first.await;
+            let superseded = matches!(stale, Err(DurableTimerError::Superseded { .. }));
+            crate::assert_with_log!(superseded, "old sleep superseded", "Superseded", stale);
+            let fire = second.await.expect("fires");
+            crate::assert_with_log!(fire.payload == b"v2", "new payload", "v2", fire.payload);
+            let acked = timers.ack(&cx, &fire).await.expect("ack");
+            let again = timers.ack(&cx, &fire).await.expect("ack");
+            crate::assert_with_log!(acked && !again, "acked once", (true, false), (acked, again));
+
+            drop(timers);
+            fs.inject(SimFsFault::Crash);
+            let timers = open(&fs, &clock).await;
+            let gone = timers.scheduled("lease:1");
+            crate::assert_with_log!(gone.is_none(), "nothing left", "None", gone);
+        });
+        crate::test_complete!("key_collision_rejects_or_reschedules");
+    }
+
+    #[test]
+    fn live_and_recovered_timers_fire_at_virtual_deadlines() {
+        init_test("live_and_recovered_timers_fire_at_virtual_deadlines");
+        let fs = SimFs::default();
+        let clock = Arc::new(VirtualClock::starting_at(EPOCH));
+        // A previous process scheduled a lease three seconds out and died.
+        block_on(async {
+            let timers = open(&fs, &clock).await;
+            let deadline = EPOCH + Duration::from_secs(3);
+            let sleep = timers
+                .durable_sleep_until(&Cx::for_testing(), "lease:3", deadline, Vec::new())
+                .await
+                .expect("schedule");
+            drop(sleep);
+        });
+        fs.inject(SimFsFault::Crash);
+
+        let mut lab = LabRuntime::new(LabConfig::new(7).with_auto_advance());
+        let root = lab.state.create_root_region(Budget::INFINITE);
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let task_fired = Arc::clone(&fired);
+        let (task, _handle) = lab
+            .state
+            .create_task(root, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("task Cx");
+                let timers = open(&fs, &clock).await;
+                timers
+                    .recover(&cx, |fire| {
+                        task_fired.lock().push((fire.key, wall_now()));
+                        async { Ok::<_, ()>(()) }
+                    })
+                    .await
+                    .expect("recover");
+
+                // The persistent clock stands still; runtime virtual time
+                // alone decides when live timers fire.
+                let deadline = clock.now() + Duration::from_secs(5);
+                let sleep = timers
+                    .durable_sleep_until(&cx, "renew:5", deadline, Vec::new())
+                    .await
+                    .expect("schedule");
+                let plain = crate::time::sleep(wall_now(), Duration::from_secs(5));
+                let fire = sleep.await.expect("fires");
+                task_fired.lock().push((fire.key.clone(), wall_now()));
+                plain.await;
+                task_fired.lock().push(("plain".to_string(), wall_now()));
+                timers.ack(&cx, &fire).await.expect("ack");
+            })
+            .expect("create task");
+        lab.scheduler.lock().schedule(task, 0);
+        lab.run_with_auto_advance();
+
+        let fired = fired.lock().clone();
+        let expected = vec![
+            ("lease:3".to_string(), Time::from_secs(3)),
+            ("renew:5".to_string(), Time::from_secs(8)),
+            ("plain".to_string(), Time::from_secs(8)),
+        ];
+        crate::assert_with_log!(fired == expected, "virtual fire times", expected, fired);
+        crate::test_complete!("live_and_recovered_timers_fire_at_virtual_deadlines");
+    }
+}
@@ -26,14 +26,51 @@ use core::fmt;
 pub struct Elapsed {
     /// The deadline that was exceeded.
     deadline: Time,
+    /// Whose deadline it was.
+    fired_by: FiredBy,
+}
+
+/// Which deadline a timeout enforced when it elapsed.
+///
+/// [`timeout`](super::timeout) clamps its requested deadline to the ambient
+/// effective deadline ([`Cx::deadline`](crate::cx::Cx::deadline)). When the
+/// clamp applies, the timeout fires on the outer bound and reports
+/// [`FiredBy::Ambient`]; callers can tell "my own budget ran out" apart from
+/// "an enclosing request/scope deadline ran out".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FiredBy {
+    /// The timeout's own requested deadline.
+    #[default]
+    Own,
+    /// A tighter enclosing deadline the requested one was clamped to.
+    Ambient,
 }
 
 impl Elapsed {
     /// Creates a new `Elapsed` error with the given deadline.
+    ///
+    /// The deadline is attributed to the timeout itself ([`FiredBy::Own`]).
     #[inline]
     #[must_use]
     pub const fn new(deadline: Time) -> Self {
-        Self { deadline }
+        Self {
+            deadline,
+            fired_by: FiredBy::Own,
+        }
+    }
+
+    /// Creates an `Elapsed` error attributed to `fired_by`.
+    #[inline]
+    #[must_use]
+    pub const fn with_fired_by(deadline: Time, fired_by: FiredBy) -> Self {
+        Self { deadline, fired_by }
+    }
+
+    /// Returns which deadline fired: the timeout's own or an ambient one.
+    #[inline]
+    #[must_use]
+    pub const fn fired_by(&self) -> FiredBy {
+        self.fired_by
     }
 
     /// Returns the deadline that was exceeded.
@@ -53,7 +90,11 @@ impl Elapsed {
 
 impl fmt::Display for Elapsed {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "deadline has elapsed at {:?}", self.deadline)
+        write!(f, "deadline has elapsed at {:?}", self.deadline)?;
+        if self.fired_by == FiredBy::Ambient {
+            f.write_str(" (ambient deadline)")?;
+        }
+        Ok(())
     }
 }
 
@@ -183,6 +224,45 @@ mod tests {
         crate::test_complete!("equality");
     }
 
+    #[test]
+    fn fired_by_attribution() {
+        init_test("fired_by_attribution");
+        let own = Elapsed::new(Time::from_secs(1));
+        let ambient = Elapsed::with_fired_by(Time::from_secs(1), FiredBy::Ambient);
+        crate::assert_with_log!(
+            own.fired_by() == FiredBy::Own,
+            "new is own",
+            FiredBy::Own,
+            own.fired_by()
+        );
+        crate::assert_with_log!(
+            ambient.fired_by() == FiredBy::Ambient,
+            "ambient attribution",
+            FiredBy::Ambient,
+            ambient.fired_by()
+        );
+        crate::assert_with_log!(
+            own != ambient,
+            "attribution is part of equality",
+            true,
+            own != ambient
+        );
+        let shown = ambient.to_string();
+        crate::assert_with_log!(
+            shown.ends_with("(ambient deadline)"),
+            "display names ambient",
+            "(ambient deadline)",
+            shown
+        );
+        crate::assert_with_log!(
+            Elapsed::default().fired_by() == FiredBy::Own,
+            "default is own",
+            FiredBy::Own,
+            Elapsed::default().fired_by()
+        );
+        crate::test_complete!("fired_by_attribution");
+    }
+
     #[test]
     fn is_error() {
         init_test("is_error");
@@ -4,6 +4,8 @@
 //! - [`Sleep`]: A future that completes after a deadline
 //! - [`TimeoutFuture`]: A wrapper that adds a timeout to any future
 //! - [`Interval`]: A repeating timer that yields at a fixed period
+//! - [`TimerBatch`]: Batched registration, reset, and cancellation of timers
+//! - [`DurableTimers`]: Timers persisted to a store that survive process restart
 //!
 //! # Virtual vs Wall Time
 //!
@@ -11,6 +13,15 @@
 //! virtual time in the lab runtime. The time source is determined by
 //! the runtime context.
 //!
+//! # Nested Deadlines
+//!
+//! Timeouts compose with the deadlines around them. [`Cx::deadline`] is the
+//! tightest of the budget deadline and every enclosing [`timeout`];
+//! [`timeout`] clamps itself to it and reports [`FiredBy::Ambient`] in the
+//! resulting [`Elapsed`] when the clamp decided the outcome.
+//!
+//! [`Cx::deadline`]: crate::cx::Cx::deadline
+//!
 //! # Cancel Safety
 //!
 //! All time primitives are cancel-safe:
@@ -44,6 +55,8 @@
 mod budget_ext;
 mod deadline;
 mod driver;
+#[cfg(not(target_arch = "wasm32"))]
+mod durable;
 mod elapsed;
 mod interval;
 pub mod intrusive_wheel;
@@ -55,15 +68,21 @@ pub use budget_ext::{BudgetTimeExt, budget_sleep, budget_timeout};
 pub use deadline::{
     DeadlineJitterDecision, DeadlineJitterPolicy, DeadlineJitterScope, with_deadline, with_timeout,
 };
+pub(crate) use driver::unix_time_millis;
 pub use driver::{
-    BrowserClockConfig, BrowserMonotonicClock, TimeSource, TimerDriver, TimerDriverApi,
-    TimerDriverHandle, TimerHandle, VirtualClock, WallClock,
+    BrowserClockConfig, BrowserMonotonicClock, TimeSource, TimerBatch, TimerDriver,
+    TimerDriverApi, TimerDriverHandle, TimerHandle, TimerResetStats, VirtualClock, WallClock,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use durable::{
+    DurableFire, DurableSleep, DurableTimerError, DurableTimers, KeyCollision, LogTimerStore,
+    RecoveryReport, TimerEntry, TimerStore,
 };
-pub use elapsed::Elapsed;
+pub use elapsed::{Elapsed, FiredBy};
 pub use interval::{Interval, MissedTickBehavior, interval, interval_at};
 pub use sleep::{Sleep, sleep, sleep_until, wall_now};
-pub use timeout_future::{TimeoutFuture, timeout, timeout_at};
+pub use timeout_future::{DEFAULT_DEADLINE_CLAMP_SLACK, TimeoutFuture, timeout, timeout_at};
 pub use wheel::{
-    CoalescingConfig, TimerDurationExceeded, TimerHandle as WheelTimerHandle, TimerWheel,
-    TimerWheelConfig,
+    CoalescingConfig, TIMER_ENTRY_BYTES, TimerClass, TimerDurationExceeded,
+    TimerHandle as WheelTimerHandle, TimerMetrics, TimerReset, TimerWheel, TimerWheelConfig,
 };
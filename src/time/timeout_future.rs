@@ -2,8 +2,10 @@
 //!
 //! The [`TimeoutFuture`] wraps another future and limits how long it can run.
 
-use super::elapsed::Elapsed;
+use super::elapsed::{Elapsed, FiredBy};
 use super::sleep::Sleep;
+use crate::cx::Cx;
+use crate::observability::LogEntry;
 use crate::types::Time;
 use pin_project::pin_project;
 use std::future::Future;
@@ -11,11 +13,35 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
-#[inline]
-fn ambient_timer_now() -> Option<Time> {
-    crate::cx::Cx::current()
-        .and_then(|cx| cx.timer_driver())
-        .map(|timer| timer.now())
+/// Default amount by which a [`timeout`] may ask for more time than the
+/// ambient deadline allows before the clamp is logged as a warning.
+///
+/// Small overshoots are routine (a fixed per-call timeout under a slightly
+/// shorter request deadline); large ones usually mean a misconfigured layer.
+pub const DEFAULT_DEADLINE_CLAMP_SLACK: Duration = Duration::from_secs(1);
+
+/// Publishes a timeout's deadline on the ambient `Cx` while its inner future
+/// is polled, so nested timeouts and [`Cx::deadline`] observe it.
+struct AmbientDeadlineFrame {
+    cx: Option<Cx>,
+    previous: Option<Time>,
+}
+
+impl AmbientDeadlineFrame {
+    fn enter(cx: Option<Cx>, deadline: Time) -> Self {
+        let previous = cx
+            .as_ref()
+            .and_then(|cx| cx.enter_timeout_deadline(deadline));
+        Self { cx, previous }
+    }
+}
+
+impl Drop for AmbientDeadlineFrame {
+    fn drop(&mut self) {
+        if let Some(cx) = &self.cx {
+            cx.leave_timeout_deadline(self.previous);
+        }
+    }
 }
 
 /// A future that wraps another future with a timeout.
@@ -65,6 +91,8 @@ pub struct TimeoutFuture<F> {
     /// Tracks whether the last terminal result was a timeout, which is the
     /// only terminal state that `reset` can safely re-arm.
     timed_out: bool,
+    /// Whose deadline this timeout enforces; see [`FiredBy`].
+    fired_by: FiredBy,
 }
 
 impl<F> TimeoutFuture<F> {
@@ -93,6 +121,7 @@ impl<F> TimeoutFuture<F> {
             sleep: Sleep::new(deadline),
             completed: false,
             timed_out: false,
+            fired_by: FiredBy::Own,
         }
     }
 
@@ -107,6 +136,7 @@ impl<F> TimeoutFuture<F> {
             sleep: Sleep::with_time_getter(deadline, time_getter),
             completed: false,
             timed_out: false,
+            fired_by: FiredBy::Own,
         }
     }
 
@@ -124,6 +154,7 @@ impl<F> TimeoutFuture<F> {
             sleep: Sleep::after(now, duration),
             completed: false,
             timed_out: false,
+            fired_by: FiredBy::Own,
         }
     }
 
@@ -134,6 +165,17 @@ impl<F> TimeoutFuture<F> {
         self.sleep.deadline()
     }
 
+    /// Returns whose deadline this timeout enforces.
+    ///
+    /// [`FiredBy::Ambient`] means [`timeout`] clamped the requested deadline
+    /// to a tighter enclosing one; an [`Elapsed`] from this future carries the
+    /// same attribution.
+    #[must_use]
+    #[inline]
+    pub const fn fired_by(&self) -> FiredBy {
+        self.fired_by
+    }
+
     /// Returns the remaining time until timeout.
     ///
     /// Returns `Duration::ZERO` if the timeout has elapsed.
@@ -177,12 +219,16 @@ impl<F> TimeoutFuture<F> {
     /// This can re-arm an uncompleted timeout or one that previously elapsed.
     /// It deliberately does not re-arm after the inner future has completed,
     /// because `TimeoutFuture` cannot replace a consumed inner future.
+    ///
+    /// The new deadline is taken as given, so it is attributed to this
+    /// timeout ([`FiredBy::Own`]).
     pub fn reset(&mut self, deadline: Time) {
         if self.completed && !self.timed_out {
             return;
         }
         self.completed = false;
         self.timed_out = false;
+        self.fired_by = FiredBy::Own;
         self.sleep.reset(deadline);
     }
 
@@ -193,11 +239,58 @@ impl<F> TimeoutFuture<F> {
         }
         self.completed = false;
         self.timed_out = false;
+        self.fired_by = FiredBy::Own;
         self.sleep.reset_after(now, duration);
     }
+
+    /// Clamps the deadline to the ambient [`Cx::deadline`], if that is
+    /// tighter, and attributes an eventual timeout to it.
+    ///
+    /// Overshooting the ambient deadline by more than the context's
+    /// [`deadline_clamp_slack`](Cx::deadline_clamp_slack) is logged as a
+    /// warning: the caller asked for time it was never going to get.
+    fn clamped_to_ambient(mut self) -> Self {
+        let Some(cx) = Cx::current() else {
+            return self;
+        };
+        let Some(ambient) = cx.deadline() else {
+            return self;
+        };
+        let requested = self.sleep.deadline();
+        if ambient >= requested {
+            return self;
+        }
+
+        let excess = Duration::from_nanos(requested.duration_since(ambient));
+        let slack = cx.deadline_clamp_slack();
+        if excess > slack {
+            crate::tracing_compat::warn!(
+                requested_ns = requested.as_nanos(),
+                ambient_ns = ambient.as_nanos(),
+                excess_ns = excess.as_nanos(),
+                slack_ns = slack.as_nanos(),
+                "timeout exceeds ambient deadline; clamped"
+            );
+            cx.log(
+                LogEntry::warn("timeout exceeds ambient deadline; clamped")
+                    .with_field("requested_ns", requested.as_nanos().to_string())
+                    .with_field("ambient_ns", ambient.as_nanos().to_string())
+                    .with_field("excess_ns", excess.as_nanos().to_string())
+                    .with_field("slack_ns", slack.as_nanos().to_string()),
+            );
+        }
+
+        self.sleep.reset(ambient);
+        self.fired_by = FiredBy::Ambient;
+        self
+    }
 }
 
 impl<F: Future + Unpin> TimeoutFuture<F> {
+    fn elapsed(&self) -> Elapsed {
+        Elapsed::with_fired_by(self.sleep.deadline(), self.fired_by)
+    }
+
     /// Polls the timeout future with an explicit time value.
     ///
     /// This is useful when you want to control the time source manually.
@@ -220,20 +313,23 @@ impl<F: Future + Unpin> TimeoutFuture<F> {
         // Fail-closed: repoll after completion returns Elapsed instead of
         // panicking so callers see a deterministic error.
         if self.completed || self.timed_out {
-            return Poll::Ready(Err(Elapsed::new(self.sleep.deadline())));
+            return Poll::Ready(Err(self.elapsed()));
         }
         let deadline = self.sleep.deadline();
         if now > deadline && self.sleep.poll_ready_with_time(now).is_ready() {
             self.completed = true;
             self.timed_out = true;
-            return Poll::Ready(Err(Elapsed::new(deadline)));
+            return Poll::Ready(Err(self.elapsed()));
         }
 
         // Prefer completed work at the exact timeout boundary. This preserves
         // the crate-wide contract that a ready inner future is not lost just
         // because the timeout boundary is observed in the same scheduler turn.
         // SAFETY: We require F: Unpin, so this is safe
-        match Pin::new(&mut self.future).poll(cx) {
+        let frame = AmbientDeadlineFrame::enter(Cx::current(), deadline);
+        let polled = Pin::new(&mut self.future).poll(cx);
+        drop(frame);
+        match polled {
             Poll::Ready(output) => {
                 self.completed = true;
                 self.timed_out = false;
@@ -245,7 +341,7 @@ impl<F: Future + Unpin> TimeoutFuture<F> {
         if self.sleep.poll_ready_with_time(now).is_ready() {
             self.completed = true;
             self.timed_out = true;
-            return Poll::Ready(Err(Elapsed::new(deadline)));
+            return Poll::Ready(Err(self.elapsed()));
         }
 
         // Preserve wake registration only when the underlying sleep can use
@@ -257,7 +353,7 @@ impl<F: Future + Unpin> TimeoutFuture<F> {
                 Poll::Ready(()) => {
                     self.completed = true;
                     self.timed_out = true;
-                    return Poll::Ready(Err(Elapsed::new(self.sleep.deadline())));
+                    return Poll::Ready(Err(self.elapsed()));
                 }
                 Poll::Pending => {}
             }
@@ -272,25 +368,33 @@ impl<F: Future> Future for TimeoutFuture<F> {
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
+        let fired_by = *this.fired_by;
         // Fail-closed: repoll after completion returns Elapsed instead of
         // panicking so callers that accidentally hold a reference see a
         // deterministic error rather than unwinding.
         if *this.completed || *this.timed_out {
-            return Poll::Ready(Err(Elapsed::new(this.sleep.deadline())));
+            return Poll::Ready(Err(Elapsed::with_fired_by(this.sleep.deadline(), fired_by)));
         }
 
         let deadline = this.sleep.deadline();
-        if let Some(now) = ambient_timer_now()
+        let ambient = Cx::current();
+        if let Some(now) = ambient
+            .as_ref()
+            .and_then(Cx::timer_driver)
+            .map(|timer| timer.now())
             && now > deadline
             && this.sleep.poll_ready_with_time(now).is_ready()
         {
             *this.completed = true;
             *this.timed_out = true;
-            return Poll::Ready(Err(Elapsed::new(deadline)));
+            return Poll::Ready(Err(Elapsed::with_fired_by(deadline, fired_by)));
         }
 
         // Prefer completed work at the exact timeout boundary.
-        match this.future.poll(cx) {
+        let frame = AmbientDeadlineFrame::enter(ambient, deadline);
+        let polled = this.future.poll(cx);
+        drop(frame);
+        match polled {
             Poll::Ready(output) => {
                 *this.completed = true;
                 *this.timed_out = false;
@@ -303,7 +407,7 @@ impl<F: Future> Future for TimeoutFuture<F> {
             Poll::Ready(()) => {
                 *this.completed = true;
                 *this.timed_out = true;
-                Poll::Ready(Err(Elapsed::new(deadline)))
+                Poll::Ready(Err(Elapsed::with_fired_by(deadline, fired_by)))
             }
             Poll::Pending => Poll::Pending,
         }
@@ -317,12 +421,19 @@ impl<F: Clone> Clone for TimeoutFuture<F> {
             sleep: self.sleep.clone(),
             completed: self.completed,
             timed_out: self.timed_out,
+            fired_by: self.fired_by,
         }
     }
 }
 
 /// Creates a `TimeoutFuture` that wraps the given future with a timeout.
 ///
+/// Timeouts compose: when called under a [`Cx`] whose effective
+/// [`deadline`](Cx::deadline) (budget or enclosing timeout) is earlier than
+/// `now + duration`, the timeout is clamped to it and reports
+/// [`FiredBy::Ambient`]. Overshooting by more than the context's
+/// [`deadline_clamp_slack`](Cx::deadline_clamp_slack) logs a warning.
+///
 /// # Arguments
 ///
 /// * `now` - The current time
@@ -342,11 +453,14 @@ impl<F: Clone> Clone for TimeoutFuture<F> {
 /// ```
 #[must_use]
 pub fn timeout<F>(now: Time, duration: Duration, future: F) -> TimeoutFuture<F> {
-    TimeoutFuture::after(now, duration, future)
+    TimeoutFuture::after(now, duration, future).clamped_to_ambient()
 }
 
 /// Creates a `TimeoutFuture` that wraps the given future with a deadline.
 ///
+/// Like [`timeout`], the deadline is clamped to the ambient
+/// [`Cx::deadline`] when that is earlier.
+///
 /// # Arguments
 ///
 /// * `deadline` - The absolute time when the timeout expires
@@ -364,7 +478,7 @@ pub fn timeout<F>(now: Time, duration: Duration, future: F) -> TimeoutFuture<F>
 /// ```
 #[must_use]
 pub fn timeout_at<F>(deadline: Time, future: F) -> TimeoutFuture<F> {
-    TimeoutFuture::new(future, deadline)
+    TimeoutFuture::new(future, deadline).clamped_to_ambient()
 }
 
 #[cfg(test)]
@@ -813,6 +927,7 @@ mod tests {
             sleep: Sleep::with_timer_driver(Time::from_secs(10), timer_driver.clone()),
             completed: false,
             timed_out: false,
+            fired_by: FiredBy::Own,
         };
         let waker = noop_waker();
         let mut cx = Context::from_waker(&waker);
@@ -958,6 +1073,264 @@ mod tests {
         assert!(matches!(result, Poll::Ready(Err(_))));
     }
 
+    // =========================================================================
+    // Ambient Deadline Tests
+    // =========================================================================
+
+    fn virtual_cx(budget: Budget) -> (Arc<VirtualClock>, Cx) {
+        let clock = Arc::new(VirtualClock::new());
+        let timer = TimerDriverHandle::with_virtual_clock(clock.clone());
+        let cx = Cx::new_with_drivers(
+            RegionId::new_for_test(0, 13),
+            TaskId::new_for_test(0, 13),
+            budget,
+            None,
+            None,
+            None,
+            Some(timer),
+            None,
+        );
+        (clock, cx)
+    }
+
+    fn clamp_warnings(collector: &crate::observability::LogCollector) -> Vec<LogEntry> {
+        collector
+            .peek()
+            .into_iter()
+            .filter(|entry| entry.message() == "timeout exceeds ambient deadline; clamped")
+            .collect()
+    }
+
+    #[test]
+    fn nested_timeouts_clamp_to_tightest_enclosing_deadline() {
+        init_test("nested_timeouts_clamp_to_tightest_enclosing_deadline");
+        let (clock, cx) = virtual_cx(Budget::INFINITE);
+        let _guard = Cx::set_current(Some(cx.clone()));
+
+        // 5s outer, 2s middle, 10s inner: the inner timeout may not outlive
+        // the middle one, and the middle one wins over the outer.
+        let mut outer = Box::pin(timeout(Time::ZERO, Duration::from_secs(5), async {
+            timeout(Time::ZERO, Duration::from_secs(2), async {
+                let inner = timeout(Time::ZERO, Duration::from_secs(10), pending::<()>());
+                let observed = (
+                    inner.deadline(),
+                    inner.fired_by(),
+                    Cx::current().and_then(|cx| cx.deadline()),
+                );
+                (observed, inner.await)
+            })
+            .await
+        }));
+        let waker = noop_waker();
+        let mut task_cx = Context::from_waker(&waker);
+
+        assert!(outer.as_mut().poll(&mut task_cx).is_pending());
+        let frames_left = cx.deadline();
+        crate::assert_with_log!(
+            frames_left.is_none(),
+            "timeout frames are scoped to the poll",
+            None::<Time>,
+            frames_left
+        );
+
+        clock.set(Time::from_secs(2));
+        let Poll::Ready(Ok(Ok(((deadline, fired_by, ambient), result)))) =
+            outer.as_mut().poll(&mut task_cx)
+        else {
+            panic!("inner timeout should fire at the middle deadline");
+        };
+        crate::assert_with_log!(
+            deadline == Time::from_secs(2),
+            "inner clamped to middle deadline",
+            Time::from_secs(2),
+            deadline
+        );
+        crate::assert_with_log!(
+            fired_by == FiredBy::Ambient,
+            "clamped timeout attributed to ambient",
+            FiredBy::Ambient,
+            fired_by
+        );
+        crate::assert_with_log!(
+            ambient == Some(Time::from_secs(2)),
+            "cx.deadline() is the tightest enclosing deadline",
+            Some(Time::from_secs(2)),
+            ambient
+        );
+        let elapsed = result.expect_err("inner timeout must elapse");
+        crate::assert_with_log!(
+            elapsed.fired_by() == FiredBy::Ambient,
+            "elapsed carries ambient attribution",
+            FiredBy::Ambient,
+            elapsed.fired_by()
+        );
+        crate::assert_with_log!(
+            elapsed.deadline() == Time::from_secs(2),
+            "elapsed reports the effective deadline",
+            Time::from_secs(2),
+            elapsed.deadline()
+        );
+        crate::test_complete!("nested_timeouts_clamp_to_tightest_enclosing_deadline");
+    }
+
+    #[test]
+    fn clamp_past_slack_logs_structured_warning() {
+        init_test("clamp_past_slack_logs_structured_warning");
+        let (_clock, cx) = virtual_cx(Budget::INFINITE.with_deadline(Time::from_secs(2)));
+        let collector = crate::observability::LogCollector::new(32)
+            .with_min_level(crate::observability::LogLevel::Trace);
+        cx.set_log_collector(collector.clone());
+        let _guard = Cx::set_current(Some(cx.clone()));
+
+        // Within the default 1s slack: clamped quietly.
+        let quiet = timeout(Time::ZERO, Duration::from_millis(2_500), ready(()));
+        crate::assert_with_log!(
+            quiet.deadline() == Time::from_secs(2),
+            "clamped within slack",
+            Time::from_secs(2),
+            quiet.deadline()
+        );
+        let warnings = clamp_warnings(&collector).len();
+        crate::assert_with_log!(warnings == 0, "no warning within slack", 0, warnings);
+
+        // 8s past the budget deadline: clamped and reported.
+        let loud = timeout(Time::ZERO, Duration::from_secs(10), ready(()));
+        crate::assert_with_log!(
+            loud.deadline() == Time::from_secs(2),
+            "clamped past slack",
+            Time::from_secs(2),
+            loud.deadline()
+        );
+        let warnings = clamp_warnings(&collector);
+        crate::assert_with_log!(warnings.len() == 1, "one warning", 1, warnings.len());
+        let entry = &warnings[0];
+        crate::assert_with_log!(
+            entry.level() == crate::observability::LogLevel::Warn,
+            "warning level",
+            crate::observability::LogLevel::Warn,
+            entry.level()
+        );
+        let fields = [
+            ("requested_ns", "10000000000"),
+            ("ambient_ns", "2000000000"),
+            ("excess_ns", "8000000000"),
+            ("slack_ns", "1000000000"),
+        ];
+        for (key, expected) in fields {
+            let actual = entry.get_field(key);
+            crate::assert_with_log!(actual == Some(expected), key, Some(expected), actual);
+        }
+
+        // A wider configured slack tolerates the same overshoot.
+        let tolerant = cx
+            .clone()
+            .with_deadline_clamp_slack(Duration::from_secs(30));
+        let _tolerant_guard = Cx::set_current(Some(tolerant));
+        let _ = timeout(Time::ZERO, Duration::from_secs(10), ready(()));
+        let warnings = clamp_warnings(&collector).len();
+        crate::assert_with_log!(warnings == 1, "slack is configurable", 1, warnings);
+        crate::test_complete!("clamp_past_slack_logs_structured_warning");
+    }
+
+    #[test]
+    fn fired_by_distinguishes_own_and_ambient_expiry() {
+        init_test("fired_by_distinguishes_own_and_ambient_expiry");
+        let (clock, cx) = virtual_cx(Budget::INFINITE.with_deadline(Time::from_secs(2)));
+        let _guard = Cx::set_current(Some(cx));
+
+        let mut own = timeout(Time::ZERO, Duration::from_secs(1), pending::<()>());
+        let mut ambient = timeout(Time::ZERO, Duration::from_secs(5), pending::<()>());
+        crate::assert_with_log!(
+            own.fired_by() == FiredBy::Own,
+            "tighter timeout keeps its own deadline",
+            FiredBy::Own,
+            own.fired_by()
+        );
+        crate::assert_with_log!(
+            ambient.fired_by() == FiredBy::Ambient,
+            "looser timeout defers to the budget",
+            FiredBy::Ambient,
+            ambient.fired_by()
+        );
+
+        let waker = noop_waker();
+        let mut task_cx = Context::from_waker(&waker);
+        clock.set(Time::from_secs(3));
+        let own_err = match Pin::new(&mut own).poll(&mut task_cx) {
+            Poll::Ready(Err(elapsed)) => elapsed,
+            other => panic!("own timeout should elapse, got {other:?}"),
+        };
+        let ambient_err = match Pin::new(&mut ambient).poll(&mut task_cx) {
+            Poll::Ready(Err(elapsed)) => elapsed,
+            other => panic!("ambient timeout should elapse, got {other:?}"),
+        };
+        crate::assert_with_log!(
+            own_err.fired_by() == FiredBy::Own,
+            "own expiry",
+            FiredBy::Own,
+            own_err.fired_by()
+        );
+        crate::assert_with_log!(
+            ambient_err.fired_by() == FiredBy::Ambient,
+            "ambient expiry",
+            FiredBy::Ambient,
+            ambient_err.fired_by()
+        );
+
+        // An explicit reset is the caller's own deadline again.
+        ambient.reset(Time::from_secs(4));
+        crate::assert_with_log!(
+            ambient.fired_by() == FiredBy::Own,
+            "reset restores own attribution",
+            FiredBy::Own,
+            ambient.fired_by()
+        );
+        crate::test_complete!("fired_by_distinguishes_own_and_ambient_expiry");
+    }
+
+    #[test]
+    fn timeout_without_ambient_deadline_is_unchanged() {
+        init_test("timeout_without_ambient_deadline_is_unchanged");
+        let (clock, cx) = virtual_cx(Budget::INFINITE);
+        let collector = crate::observability::LogCollector::new(32)
+            .with_min_level(crate::observability::LogLevel::Trace);
+        cx.set_log_collector(collector.clone());
+        let _guard = Cx::set_current(Some(cx));
+
+        let mut t = timeout(Time::ZERO, Duration::from_secs(10), pending::<()>());
+        crate::assert_with_log!(
+            t.deadline() == Time::from_secs(10),
+            "requested deadline kept",
+            Time::from_secs(10),
+            t.deadline()
+        );
+        crate::assert_with_log!(
+            t.fired_by() == FiredBy::Own,
+            "own attribution",
+            FiredBy::Own,
+            t.fired_by()
+        );
+        let warnings = clamp_warnings(&collector).len();
+        crate::assert_with_log!(warnings == 0, "no warning", 0, warnings);
+
+        let waker = noop_waker();
+        let mut task_cx = Context::from_waker(&waker);
+        clock.set(Time::from_secs(9));
+        assert!(Pin::new(&mut t).poll(&mut task_cx).is_pending());
+        clock.set(Time::from_secs(11));
+        let fired_by = match Pin::new(&mut t).poll(&mut task_cx) {
+            Poll::Ready(Err(elapsed)) => elapsed.fired_by(),
+            other => panic!("timeout should elapse, got {other:?}"),
+        };
+        crate::assert_with_log!(
+            fired_by == FiredBy::Own,
+            "own expiry",
+            FiredBy::Own,
+            fired_by
+        );
+        crate::test_complete!("timeout_without_ambient_deadline_is_unchanged");
+    }
+
     // =========================================================================
     // Clone Tests
     // =========================================================================
@@ -21,13 +21,28 @@
 //! is reached. This is useful for reducing CPU overhead when many timers have similar
 //! deadlines.
 //!
+//! # Expiration Order
+//!
+//! Timers expired by one [`TimerWheel::collect_expired`] call fire in a
+//! deterministic order: earliest deadline first, then by [`TimerClass`]
+//! (registered via [`TimerWheel::register_with_class`]), then in
+//! registration order. A [`TimerWheel::reset`] keeps the timer's original
+//! registration rank. The order is the same under wall and virtual time.
+//!
+//! # Memory
+//!
+//! Each outstanding timer costs at most [`TIMER_ENTRY_BYTES`] (56 bytes on
+//! 64-bit targets): a 32-byte wheel entry holding the waker, plus a liveness
+//! record in a slab. At 10M outstanding timers that is ~534 MiB, before
+//! bucket `Vec` growth slack.
+//!
 //! # Performance Characteristics
 //!
 //! - Insert: O(1) - direct slot calculation
 //! - Cancel: O(1) - generation-based invalidation
 //! - Tick (no expiry): O(1) - cursor advance
-//! - Tick (with expiry): O(expired) - returns wakers
-//! - Space: O(SLOTS × LEVELS) + O(overflow timers)
+//! - Tick (with expiry): O(expired × log expired) - returns wakers in order
+//! - Space: O(SLOTS × LEVELS) + O(outstanding timers)
 
 use crate::types::Time;
 use smallvec::SmallVec;
@@ -206,30 +221,107 @@ pub struct TimerDurationExceeded {
 /// Opaque handle for a scheduled timer.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TimerHandle {
-    id: u64,
-    generation: u64,
+    id: u32,
+    generation: u32,
 }
 
 impl TimerHandle {
     /// Returns the timer identifier.
     #[must_use]
     pub const fn id(&self) -> u64 {
-        self.id
+        self.id as u64
     }
 
     /// Returns the generation associated with this handle.
     #[must_use]
     pub const fn generation(&self) -> u64 {
-        self.generation
+        self.generation as u64
     }
 }
 
+/// Dispatch class of a timer, consulted when timers share a deadline.
+///
+/// Among timers expiring at the same instant, lower classes fire first;
+/// the class never makes a timer fire before its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TimerClass {
+    /// Latency-critical work, dispatched first.
+    Critical,
+    /// High-priority work.
+    High,
+    /// Default class for plain registrations.
+    #[default]
+    Normal,
+    /// Best-effort work, dispatched last.
+    BestEffort,
+}
+
+impl TimerClass {
+    /// Number of timer classes.
+    pub const COUNT: usize = 4;
+
+    /// All classes in dispatch order.
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Critical, Self::High, Self::Normal, Self::BestEffort];
+
+    /// Returns this class's position in [`ALL`](Self::ALL).
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns a stable lowercase name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::BestEffort => "best_effort",
+        }
+    }
+}
+
+impl std::fmt::Display for TimerClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outstanding-timer counts of a wheel or driver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimerMetrics {
+    /// Timers registered that have neither fired nor been cancelled.
+    pub outstanding: usize,
+    /// `outstanding` split by class, indexed by [`TimerClass::index`].
+    pub by_class: [usize; TimerClass::COUNT],
+}
+
+impl TimerMetrics {
+    /// Returns the outstanding count for `class`.
+    #[must_use]
+    pub const fn class(&self, class: TimerClass) -> usize {
+        self.by_class[class.index()]
+    }
+}
+
+/// How [`TimerWheel::reset`] moved a timer to its new deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimerReset {
+    /// The new deadline maps to the slot the timer already occupies, so only
+    /// its stored deadline changed.
+    InPlace,
+    /// The timer was taken out of its slot (or the ready/overflow queue) and
+    /// inserted again at the new deadline.
+    Reinserted,
+}
+
 #[derive(Debug, Clone)]
 struct TimerEntry {
     deadline: Time,
     waker: Waker,
-    id: u64,
-    generation: u64,
+    id: u32,
+    generation: u32,
 }
 
 #[derive(Debug)]
@@ -238,7 +330,88 @@ struct OverflowEntry {
     entry: TimerEntry,
 }
 
-type TimerActivityMap = slab::Slab<u64>;
+/// Liveness record for a registered timer.
+///
+/// `slot` remembers where the timer was last inserted into a wheel level so
+/// [`TimerWheel::reset`] can find it without scanning. It is only a hint:
+/// buckets drained by a tick or cascade, and entries moved by coalescing,
+/// leave it stale, so every use re-checks the entry found at that index.
+#[derive(Debug, Clone, Copy)]
+struct ActiveTimer {
+    generation: u32,
+    slot: SlotHint,
+    class: TimerClass,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SlotIndex {
+    level: usize,
+    slot: usize,
+    index: usize,
+}
+
+/// A [`SlotIndex`] packed into 32 bits: 2 bits of level, 8 of slot and 22
+/// of index. Entries deeper in a bucket than the index field can name get
+/// no hint and are found by the fallback scan in [`TimerWheel::locate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SlotHint(u32);
+
+impl SlotHint {
+    const NONE: Self = Self(u32::MAX);
+    const INDEX_BITS: u32 = 22;
+    const INDEX_MASK: u32 = (1 << Self::INDEX_BITS) - 1;
+
+    fn new(at: SlotIndex) -> Self {
+        // The all-ones index is reserved so no hint collides with `NONE`.
+        if at.index >= Self::INDEX_MASK as usize {
+            return Self::NONE;
+        }
+        Self(((at.level as u32) << 30) | ((at.slot as u32) << Self::INDEX_BITS) | at.index as u32)
+    }
+
+    fn get(self) -> Option<SlotIndex> {
+        if self == Self::NONE {
+            return None;
+        }
+        Some(SlotIndex {
+            level: (self.0 >> 30) as usize,
+            slot: ((self.0 >> Self::INDEX_BITS) & 0xFF) as usize,
+            index: (self.0 & Self::INDEX_MASK) as usize,
+        })
+    }
+}
+
+/// Upper bound on the bytes one outstanding timer costs: its wheel entry
+/// plus its slab slot (the liveness record or a free-list link, and a
+/// discriminant).
+pub const TIMER_ENTRY_BYTES: usize =
+    std::mem::size_of::<TimerEntry>() + slab_slot_bytes(std::mem::size_of::<ActiveTimer>());
+
+const fn slab_slot_bytes(record: usize) -> usize {
+    let word = std::mem::size_of::<usize>();
+    let payload = if record > word { record } else { word };
+    payload.next_multiple_of(word) + word
+}
+
+const _: () = assert!(TIMER_ENTRY_BYTES < 64, "timer entry outgrew its 64-byte budget");
+
+/// Where an entry with a given deadline goes when inserted now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    Ready,
+    Slot { level: usize, slot: usize },
+    Overflow,
+}
+
+/// Where a live entry currently sits.
+#[derive(Debug, Clone, Copy)]
+enum EntryLocation {
+    Ready(usize),
+    Slot(SlotIndex),
+    Overflow,
+}
+
+type TimerActivityMap = slab::Slab<ActiveTimer>;
 
 impl PartialEq for OverflowEntry {
     fn eq(&self, other: &Self) -> bool {
@@ -375,8 +548,9 @@ pub struct TimerWheel {
     levels: [WheelLevel; LEVEL_COUNT],
     overflow: BinaryHeap<OverflowEntry>,
     ready: Vec<TimerEntry>,
-    next_generation: u64,
+    next_generation: u32,
     active: TimerActivityMap,
+    class_counts: [usize; TimerClass::COUNT],
     config: TimerWheelConfig,
     coalescing: CoalescingConfig,
     max_wheel_duration_ns: u64,
@@ -420,6 +594,7 @@ impl TimerWheel {
             ready: Vec::with_capacity(8),
             next_generation: 0,
             active: slab::Slab::with_capacity(64),
+            class_counts: [0; TimerClass::COUNT],
             config,
             coalescing,
             max_wheel_duration_ns,
@@ -451,9 +626,19 @@ impl TimerWheel {
         self.active.is_empty()
     }
 
+    /// Returns outstanding-timer counts, split by class.
+    #[must_use]
+    pub fn metrics(&self) -> TimerMetrics {
+        TimerMetrics {
+            outstanding: self.active.len(),
+            by_class: self.class_counts,
+        }
+    }
+
     /// Removes all timers from the wheel.
     pub fn clear(&mut self) {
         self.active.clear();
+        self.class_counts = [0; TimerClass::COUNT];
         self.ready.clear();
         self.overflow.clear();
         for level in &mut self.levels {
@@ -488,15 +673,34 @@ impl TimerWheel {
     /// silently clamped to the maximum allowed duration. The timer will fire
     /// early, and the caller is expected to check if the true deadline has
     /// been reached and re-register if necessary.
-    pub fn register(&mut self, mut deadline: Time, waker: Waker) -> TimerHandle {
+    pub fn register(&mut self, deadline: Time, waker: Waker) -> TimerHandle {
+        self.register_with_class(deadline, waker, TimerClass::Normal)
+    }
+
+    /// Registers a timer in `class`, consulted for dispatch order among
+    /// timers that expire at the same deadline.
+    ///
+    /// Clamps the deadline like [`register`](Self::register).
+    pub fn register_with_class(
+        &mut self,
+        deadline: Time,
+        waker: Waker,
+        class: TimerClass,
+    ) -> TimerHandle {
         let current = self.current_time();
+        let deadline = self.clamp_deadline(deadline, current);
+        self.insert_validated(deadline, waker, current, class)
+    }
+
+    /// Clamps `deadline` to the configured maximum timer duration.
+    fn clamp_deadline(&self, deadline: Time, current: Time) -> Time {
         if deadline > current {
             let duration_ns = deadline.as_nanos().saturating_sub(current.as_nanos());
             if duration_ns > self.max_timer_duration_ns {
-                deadline = current.saturating_add_nanos(self.max_timer_duration_ns);
+                return current.saturating_add_nanos(self.max_timer_duration_ns);
             }
         }
-        self.insert_validated(deadline, waker, current)
+        deadline
     }
 
     /// Attempts to register a timer with validation.
@@ -518,16 +722,27 @@ impl TimerWheel {
                 });
             }
         }
-        Ok(self.insert_validated(deadline, waker, current))
+        Ok(self.insert_validated(deadline, waker, current, TimerClass::Normal))
     }
 
     /// Inserts a timer whose deadline has already been validated/clamped by
     /// the caller. Avoids a redundant `current_time()` read on the hot path.
-    fn insert_validated(&mut self, deadline: Time, waker: Waker, current: Time) -> TimerHandle {
+    fn insert_validated(
+        &mut self,
+        deadline: Time,
+        waker: Waker,
+        current: Time,
+        class: TimerClass,
+    ) -> TimerHandle {
         let generation = self.next_generation;
         self.next_generation = self.next_generation.wrapping_add(1);
 
-        let id = self.active.insert(generation) as u64;
+        let id = self.active.insert(ActiveTimer {
+            generation,
+            slot: SlotHint::NONE,
+            class,
+        }) as u32;
+        self.class_counts[class.index()] += 1;
 
         let entry = TimerEntry {
             deadline,
@@ -555,9 +770,10 @@ impl TimerWheel {
         if self
             .active
             .get(id_usize)
-            .is_some_and(|&g| g == handle.generation)
+            .is_some_and(|active| active.generation == handle.generation)
         {
-            self.active.remove(id_usize);
+            let removed = self.active.remove(id_usize);
+            self.class_counts[removed.class.index()] -= 1;
             if self.active.is_empty() {
                 self.purge_inactive_storage();
             }
@@ -567,6 +783,109 @@ impl TimerWheel {
         }
     }
 
+    /// Moves an active timer to `deadline`, keeping its handle and waker.
+    ///
+    /// When the new deadline maps to the wheel slot the timer already
+    /// occupies, only the stored deadline is rewritten
+    /// ([`TimerReset::InPlace`]); otherwise the entry is taken out and
+    /// inserted again ([`TimerReset::Reinserted`]). Either way the timer
+    /// fires exactly when a fresh registration at `deadline` would. The
+    /// deadline is clamped like [`register`](Self::register).
+    ///
+    /// Returns `None` if the handle is no longer active (fired or cancelled).
+    pub fn reset(&mut self, handle: &TimerHandle, deadline: Time) -> Option<TimerReset> {
+        let location = self.locate(handle)?;
+        let current = self.current_time();
+        let deadline = self.clamp_deadline(deadline, current);
+
+        if let EntryLocation::Slot(at) = location {
+            let in_same_slot = matches!(
+                self.placement(deadline, current),
+                Placement::Slot { level, slot } if level == at.level && slot == at.slot
+            );
+            if in_same_slot {
+                self.levels[at.level].slots[at.slot][at.index].deadline = deadline;
+                return Some(TimerReset::InPlace);
+            }
+        }
+
+        let mut entry = self.take_entry(handle, location);
+        entry.deadline = deadline;
+        self.insert_entry_at(entry, current);
+        Some(TimerReset::Reinserted)
+    }
+
+    /// Finds the live entry for `handle`, trying the slot hint first.
+    fn locate(&self, handle: &TimerHandle) -> Option<EntryLocation> {
+        let active = self.active.get(handle.id as usize)?;
+        if active.generation != handle.generation {
+            return None;
+        }
+        let is_handle =
+            |entry: &TimerEntry| entry.id == handle.id && entry.generation == handle.generation;
+
+        if let Some(at) = active.slot.get() {
+            let hinted = self.levels[at.level].slots[at.slot].get(at.index);
+            if hinted.is_some_and(is_handle) {
+                return Some(EntryLocation::Slot(at));
+            }
+        }
+        if let Some(index) = self.ready.iter().position(is_handle) {
+            return Some(EntryLocation::Ready(index));
+        }
+        if self.overflow.iter().any(|entry| is_handle(&entry.entry)) {
+            return Some(EntryLocation::Overflow);
+        }
+        // The hint went stale without the entry leaving the wheel (coalescing
+        // compacts slots in place); fall back to a scan of occupied slots.
+        for (level_idx, level) in self.levels.iter().enumerate() {
+            for (slot, bucket) in level.slots.iter().enumerate() {
+                if let Some(index) = bucket.iter().position(is_handle) {
+                    return Some(EntryLocation::Slot(SlotIndex {
+                        level: level_idx,
+                        slot,
+                        index,
+                    }));
+                }
+            }
+        }
+        None
+    }
+
+    /// Removes the entry for `handle`, found at `location`, from wheel storage.
+    fn take_entry(&mut self, handle: &TimerHandle, location: EntryLocation) -> TimerEntry {
+        match location {
+            EntryLocation::Ready(index) => self.ready.remove(index),
+            EntryLocation::Slot(at) => {
+                let level = &mut self.levels[at.level];
+                let bucket = &mut level.slots[at.slot];
+                let entry = bucket.swap_remove(at.index);
+                if let Some(moved) = bucket.get(at.index)
+                    && let Some(active) = self.active.get_mut(moved.id as usize)
+                    && active.generation == moved.generation
+                {
+                    active.slot = SlotHint::new(at);
+                }
+                if bucket.is_empty() {
+                    level.clear_occupied(at.slot);
+                }
+                entry
+            }
+            EntryLocation::Overflow => {
+                let mut entries = std::mem::take(&mut self.overflow).into_vec();
+                let index = entries
+                    .iter()
+                    .position(|entry| {
+                        entry.entry.id == handle.id && entry.entry.generation == handle.generation
+                    })
+                    .expect("located overflow entry missing");
+                let entry = entries.swap_remove(index).entry;
+                self.overflow = entries.into();
+                entry
+            }
+        }
+    }
+
     /// Returns the earliest pending deadline, if any.
     #[must_use]
     pub fn next_deadline(&mut self) -> Option<Time> {
@@ -648,26 +967,46 @@ impl TimerWheel {
     }
 
     fn insert_entry_at(&mut self, entry: TimerEntry, current: Time) {
-        if entry.deadline <= current {
-            self.ready.push(entry);
-            return;
+        match self.placement(entry.deadline, current) {
+            Placement::Ready => self.ready.push(entry),
+            Placement::Overflow => self.overflow.push(OverflowEntry {
+                deadline: entry.deadline,
+                entry,
+            }),
+            Placement::Slot { level, slot } => {
+                let wheel_level = &mut self.levels[level];
+                let bucket = &mut wheel_level.slots[slot];
+                if let Some(active) = self.active.get_mut(entry.id as usize)
+                    && active.generation == entry.generation
+                {
+                    active.slot = SlotHint::new(SlotIndex {
+                        level,
+                        slot,
+                        index: bucket.len(),
+                    });
+                }
+                bucket.push(entry);
+                wheel_level.set_occupied(slot);
+            }
+        }
+    }
+
+    /// Decides where an entry with `deadline` goes relative to `current`.
+    fn placement(&self, deadline: Time, current: Time) -> Placement {
+        if deadline <= current {
+            return Placement::Ready;
         }
 
-        let delta = entry.deadline.as_nanos().saturating_sub(current.as_nanos());
+        let delta = deadline.as_nanos().saturating_sub(current.as_nanos());
 
         // Check against configured max_wheel_duration for overflow
-        let max_range = self.max_range_ns();
-        if delta >= max_range {
-            self.overflow.push(OverflowEntry {
-                deadline: entry.deadline,
-                entry,
-            });
-            return;
+        if delta >= self.max_range_ns() {
+            return Placement::Overflow;
         }
 
-        for (idx, level) in self.levels.iter_mut().enumerate() {
+        for (idx, level) in self.levels.iter().enumerate() {
             if delta < level.range_ns() {
-                let tick = entry.deadline.as_nanos() / level.resolution_ns;
+                let tick = deadline.as_nanos() / level.resolution_ns;
 
                 // For Level 0, if the calculated tick matches the current tick (or is older),
                 // it means the deadline is within the current millisecond window.
@@ -675,22 +1014,16 @@ impl TimerWheel {
                 if idx == 0 {
                     let current_tick_l0 = current.as_nanos() / level.resolution_ns;
                     if tick <= current_tick_l0 {
-                        self.ready.push(entry);
-                        return;
+                        return Placement::Ready;
                     }
                 }
 
                 let slot = (tick % (SLOTS_PER_LEVEL as u64)) as usize;
-                level.slots[slot].push(entry);
-                level.set_occupied(slot);
-                return;
+                return Placement::Slot { level: idx, slot };
             }
         }
 
-        self.overflow.push(OverflowEntry {
-            deadline: entry.deadline,
-            entry,
-        });
+        Placement::Overflow
     }
 
     fn advance_to(&mut self, target_tick: u64) {
@@ -916,6 +1249,7 @@ impl TimerWheel {
         }
 
         let mut wakers = WakerBatch::new();
+        let mut fired: SmallVec<[(TimerEntry, TimerClass); 4]> = SmallVec::new();
 
         // Take the ready vec out so we can mutate it in-place while also
         // accessing self.active / self.coalescing through &mut self.
@@ -971,13 +1305,23 @@ impl TimerWheel {
             };
 
             if should_fire {
-                self.active.remove(entry.id as usize);
-                wakers.push(entry.waker);
+                let removed = self.active.remove(entry.id as usize);
+                self.class_counts[removed.class.index()] -= 1;
+                fired.push((entry, removed.class));
             } else {
                 self.insert_entry(entry);
             }
         }
 
+        // Deadline, then class, then registration order. Ages count back
+        // from the next generation, so the key stays total across wrap.
+        let next_generation = self.next_generation;
+        fired.sort_unstable_by_key(|(entry, class)| {
+            let age = next_generation.wrapping_sub(entry.generation);
+            (entry.deadline, *class, std::cmp::Reverse(age))
+        });
+        wakers.extend(fired.into_iter().map(|(entry, _)| entry.waker));
+
         // Put the vec back — retains its capacity for the next tick.
         let mut new_ready = std::mem::take(&mut self.ready);
         ready.append(&mut new_ready);
@@ -1070,7 +1414,7 @@ impl TimerWheel {
     fn is_live(&self, entry: &TimerEntry) -> bool {
         self.active
             .get(entry.id as usize)
-            .is_some_and(|generation| *generation == entry.generation)
+            .is_some_and(|active| active.generation == entry.generation)
     }
 
     /// Returns the maximum range in nanoseconds for direct wheel storage.
@@ -1375,7 +1719,7 @@ mod tests {
     fn wheel_register_wraps_id_and_generation_without_immediate_collision() {
         init_test("wheel_register_wraps_id_and_generation_without_immediate_collision");
         let mut wheel = TimerWheel::new();
-        wheel.next_generation = u64::MAX;
+        wheel.next_generation = u32::MAX;
 
         let h1 = wheel.register(
             Time::from_millis(5),
@@ -1387,9 +1731,9 @@ mod tests {
         );
 
         crate::assert_with_log!(
-            h1.generation == u64::MAX,
+            h1.generation == u32::MAX,
             "first generation",
-            u64::MAX,
+            u32::MAX,
             h1.generation
         );
         crate::assert_with_log!(
@@ -3186,6 +3530,250 @@ mod tests {
 
         crate::test_complete!("conformance_coalescing_group_behavior");
     }
+
+    #[test]
+    fn reset_within_slot_rewrites_deadline_in_place() {
+        init_test("reset_within_slot_rewrites_deadline_in_place");
+        let mut wheel = TimerWheel::new();
+        let counter = Arc::new(AtomicU64::new(0));
+        // 10s lands in a 256ms level-1 slot; 10.1s maps to the same slot.
+        let handle = wheel.register(Time::from_secs(10), counter_waker(counter.clone()));
+
+        let reset = wheel.reset(&handle, Time::from_millis(10_100));
+        crate::assert_with_log!(
+            reset == Some(TimerReset::InPlace),
+            "same slot resets in place",
+            Some(TimerReset::InPlace),
+            reset
+        );
+
+        let early = wheel.collect_expired(Time::from_secs(10));
+        crate::assert_with_log!(early.is_empty(), "old deadline no longer fires", 0, early.len());
+        let fired = wheel.collect_expired(Time::from_millis(10_100));
+        crate::assert_with_log!(fired.len() == 1, "fires at new deadline", 1, fired.len());
+        let active = wheel.cancel(&handle);
+        crate::assert_with_log!(!active, "handle already fired", false, active);
+        crate::test_complete!("reset_within_slot_rewrites_deadline_in_place");
+    }
+
+    #[test]
+    fn reset_across_slots_reinserts_and_keeps_handle() {
+        init_test("reset_across_slots_reinserts_and_keeps_handle");
+        let mut wheel = TimerWheel::new();
+        let counter = Arc::new(AtomicU64::new(0));
+        let handle = wheel.register(Time::from_secs(10), counter_waker(counter.clone()));
+        let other = wheel.register(Time::from_millis(10_050), counter_waker(counter.clone()));
+
+        // Earlier, later, and back into the ready queue.
+        for (deadline, expected) in [
+            (Time::from_secs(2), TimerReset::Reinserted),
+            (Time::from_secs(30), TimerReset::Reinserted),
+            (Time::ZERO, TimerReset::Reinserted),
+            (Time::from_secs(40), TimerReset::Reinserted),
+        ] {
+            let reset = wheel.reset(&handle, deadline);
+            crate::assert_with_log!(reset == Some(expected), "reinserted", expected, reset);
+        }
+        crate::assert_with_log!(wheel.len() == 2, "no duplicate entries", 2, wheel.len());
+
+        let fired = wheel.collect_expired(Time::from_secs(39));
+        crate::assert_with_log!(fired.len() == 1, "only the untouched timer", 1, fired.len());
+        let fired = wheel.collect_expired(Time::from_secs(40));
+        crate::assert_with_log!(fired.len() == 1, "reset timer fires once", 1, fired.len());
+        let active = wheel.cancel(&other);
+        crate::assert_with_log!(!active, "other timer fired", false, active);
+        crate::test_complete!("reset_across_slots_reinserts_and_keeps_handle");
+    }
+
+    #[test]
+    fn reset_moves_overflow_timers_and_ignores_inactive_handles() {
+        init_test("reset_moves_overflow_timers_and_ignores_inactive_handles");
+        let mut wheel = TimerWheel::new();
+        let counter = Arc::new(AtomicU64::new(0));
+        let far = wheel.register(Time::from_secs(48 * 3600), counter_waker(counter.clone()));
+        let overflow = wheel.overflow_count();
+        crate::assert_with_log!(overflow == 1, "parked in overflow", 1, overflow);
+
+        let reset = wheel.reset(&far, Time::from_secs(5));
+        crate::assert_with_log!(
+            reset == Some(TimerReset::Reinserted),
+            "overflow timer reinserted",
+            Some(TimerReset::Reinserted),
+            reset
+        );
+        let fired = wheel.collect_expired(Time::from_secs(5));
+        crate::assert_with_log!(fired.len() == 1, "fires at new deadline", 1, fired.len());
+
+        let cancelled = wheel.register(Time::from_secs(10), counter_waker(counter.clone()));
+        wheel.cancel(&cancelled);
+        for handle in [far, cancelled] {
+            let reset = wheel.reset(&handle, Time::from_secs(20));
+            crate::assert_with_log!(reset.is_none(), "inactive handle", None::<TimerReset>, reset);
+        }
+        crate::assert_with_log!(wheel.is_empty(), "nothing revived", true, wheel.is_empty());
+        crate::test_complete!("reset_moves_overflow_timers_and_ignores_inactive_handles");
+    }
+
+    #[test]
+    fn timer_entry_fits_size_budget() {
+        init_test("timer_entry_fits_size_budget");
+        let total = TIMER_ENTRY_BYTES;
+        crate::assert_with_log!(total < 64, "under 64 bytes", "< 64", total);
+        #[cfg(target_pointer_width = "64")]
+        {
+            let entry = std::mem::size_of::<TimerEntry>();
+            crate::assert_with_log!(entry == 32, "wheel entry", 32, entry);
+            let active = std::mem::size_of::<ActiveTimer>();
+            crate::assert_with_log!(active == 12, "slab record", 12, active);
+            crate::assert_with_log!(total == 56, "total", 56, total);
+        }
+        crate::test_complete!("timer_entry_fits_size_budget");
+    }
+
+    #[test]
+    fn slot_hint_round_trips_every_level() {
+        init_test("slot_hint_round_trips_every_level");
+        for level in 0..LEVEL_COUNT {
+            for slot in [0, 1, SLOTS_PER_LEVEL - 1] {
+                for index in [0, 1, 4_095] {
+                    let hint = SlotIndex { level, slot, index };
+                    let packed = SlotHint::new(hint).get();
+                    crate::assert_with_log!(packed == Some(hint), "round trip", hint, packed);
+                }
+            }
+        }
+        let none = SlotHint::NONE.get();
+        crate::assert_with_log!(none.is_none(), "none", "None", none);
+        crate::test_complete!("slot_hint_round_trips_every_level");
+    }
+
+    /// A waker that appends its tag to a shared log when woken.
+    struct TagWaker {
+        tag: usize,
+        log: Arc<std::sync::Mutex<Vec<usize>>>,
+    }
+
+    impl Wake for TagWaker {
+        fn wake(self: Arc<Self>) {
+            self.log.lock().unwrap().push(self.tag);
+        }
+    }
+
+    fn tag_waker(tag: usize, log: &Arc<std::sync::Mutex<Vec<usize>>>) -> Waker {
+        Arc::new(TagWaker {
+            tag,
+            log: log.clone(),
+        })
+        .into()
+    }
+
+    #[test]
+    fn expiry_order_is_deadline_class_then_registration() {
+        init_test("expiry_order_is_deadline_class_then_registration");
+        for seed in 0..32_u64 {
+            let mut rng = crate::util::DetRng::new(seed);
+            let mut wheel = TimerWheel::new();
+            let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+            // (deadline, class, live) per registration sequence number.
+            let mut timers: Vec<(Time, TimerClass, bool)> = Vec::new();
+            let mut handles = Vec::new();
+            let mut now = Time::ZERO;
+
+            for _round in 0..8 {
+                // Register a burst with heavy deadline collisions, sometimes
+                // far enough out to land in the upper levels.
+                for _ in 0..rng.next_usize(64) + 1 {
+                    let spread = if rng.next_bool() { 16 } else { 4_000 };
+                    let offset = (rng.next_usize(spread) as u64 + 1) * 1_000_000;
+                    let deadline = now.saturating_add_nanos(offset);
+                    let class = TimerClass::ALL[rng.next_usize(TimerClass::COUNT)];
+                    let tag = timers.len();
+                    let handle = wheel.register_with_class(deadline, tag_waker(tag, &log), class);
+                    timers.push((deadline, class, true));
+                    handles.push(handle);
+                }
+                // Cancel a few outstanding timers.
+                for _ in 0..rng.next_usize(4) {
+                    let tag = rng.next_usize(handles.len());
+                    if wheel.cancel(&handles[tag]) {
+                        timers[tag].2 = false;
+                    }
+                }
+                // Advance by a random step and check each fired batch.
+                now = now.saturating_add_nanos(rng.next_usize(2_000) as u64 * 1_000_000);
+                let batch = wheel.collect_expired(now);
+                for waker in batch {
+                    waker.wake();
+                }
+                let fired = std::mem::take(&mut *log.lock().unwrap());
+                let sorted = fired.windows(2).all(|w| {
+                    let (a, b) = (&timers[w[0]], &timers[w[1]]);
+                    (a.0, a.1, w[0]) < (b.0, b.1, w[1])
+                });
+                crate::assert_with_log!(sorted, "batch ordered", true, fired);
+                for tag in fired {
+                    let (deadline, _, live) = timers[tag];
+                    crate::assert_with_log!(live, "fires once, never cancelled", true, tag);
+                    crate::assert_with_log!(deadline <= now, "not early", now, deadline);
+                    timers[tag].2 = false;
+                }
+                let outstanding = timers.iter().filter(|t| t.2).count();
+                let metrics = wheel.metrics();
+                crate::assert_with_log!(
+                    metrics.outstanding == outstanding,
+                    "metrics track outstanding",
+                    outstanding,
+                    metrics
+                );
+            }
+
+            // Flush: everything still live fires, in order, exactly once.
+            let end = now.saturating_add_nanos(10_000_000_000);
+            for waker in wheel.collect_expired(end) {
+                waker.wake();
+            }
+            let fired = std::mem::take(&mut *log.lock().unwrap());
+            let mut expected: Vec<usize> = (0..timers.len()).filter(|&t| timers[t].2).collect();
+            expected.sort_by_key(|&t| (timers[t].0, timers[t].1, t));
+            crate::assert_with_log!(fired == expected, "flush order", expected, fired);
+            crate::assert_with_log!(wheel.is_empty(), "drained", true, wheel.len());
+        }
+        crate::test_complete!("expiry_order_is_deadline_class_then_registration");
+    }
+
+    #[test]
+    fn metrics_break_down_outstanding_by_class() {
+        init_test("metrics_break_down_outstanding_by_class");
+        let mut wheel = TimerWheel::new();
+        let waker = Waker::noop().clone();
+        let critical =
+            wheel.register_with_class(Time::from_millis(5), waker.clone(), TimerClass::Critical);
+        let _ = wheel.register(Time::from_millis(5), waker.clone());
+        let _ = wheel.register_with_class(Time::from_millis(50), waker, TimerClass::BestEffort);
+
+        let metrics = wheel.metrics();
+        crate::assert_with_log!(metrics.outstanding == 3, "outstanding", 3, metrics);
+        let critical_count = metrics.class(TimerClass::Critical);
+        crate::assert_with_log!(critical_count == 1, "critical", 1, critical_count);
+        let high = metrics.class(TimerClass::High);
+        crate::assert_with_log!(high == 0, "high", 0, high);
+
+        let cancelled = wheel.cancel(&critical);
+        crate::assert_with_log!(cancelled, "cancel critical", true, cancelled);
+        let fired = wheel.collect_expired(Time::from_millis(10)).len();
+        crate::assert_with_log!(fired == 1, "normal fired", 1, fired);
+
+        let metrics = wheel.metrics();
+        let expected = TimerMetrics {
+            outstanding: 1,
+            by_class: [0, 0, 0, 1],
+        };
+        crate::assert_with_log!(metrics == expected, "best-effort left", expected, metrics);
+        wheel.clear();
+        let cleared = wheel.metrics();
+        crate::assert_with_log!(cleared == TimerMetrics::default(), "cleared", "zero", cleared);
+        crate::test_complete!("metrics_break_down_outstanding_by_class");
+    }
 }
 
 #[cfg(test)]
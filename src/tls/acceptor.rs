@@ -4,9 +4,20 @@
 //! TLS connections on the server side.
 
 use super::error::TlsError;
+#[cfg(feature = "tls")]
+use super::reload::{
+    CertificateClock, CertificateEvent, CertificateEventListener, CertificateMetadata,
+    CertificateReloader, ChainPolicy, DEFAULT_EXPIRY_WARNING_WINDOW, ReloadOptions,
+};
 use super::stream::TlsStream;
 use super::types::{CertificateChain, PrivateKey, RootCertStore};
+#[cfg(feature = "tls")]
+use crate::cx::Cx;
 use crate::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "tls")]
+use crate::time::TimeSource;
+#[cfg(feature = "tls")]
+use crate::types::Time;
 
 #[cfg(feature = "tls")]
 use rustls::ServerConfig;
@@ -19,7 +30,11 @@ use std::collections::BTreeMap;
 use std::future::poll_fn;
 use std::path::Path;
 #[cfg(feature = "tls")]
+use std::path::PathBuf;
+#[cfg(feature = "tls")]
 use std::sync::Arc;
+#[cfg(feature = "tls")]
+use std::time::Duration;
 
 /// Server-side TLS acceptor.
 ///
@@ -81,6 +96,10 @@ pub struct TlsAcceptor {
     /// 0-RTT early data. Applications can use this to implement
     /// protection logic at the request processing layer.
     early_data_replay_protection: EarlyDataReplayProtection,
+    /// Swaps the served certificate at runtime. `None` for acceptors made
+    /// from a raw `ServerConfig`, whose certificate is fixed.
+    #[cfg(feature = "tls")]
+    reloader: Option<Arc<CertificateReloader>>,
     #[cfg(not(feature = "tls"))]
     _marker: std::marker::PhantomData<()>,
 }
@@ -96,6 +115,7 @@ impl TlsAcceptor {
             require_sni: false,
             sni_alpn_allow_list: None,
             early_data_replay_protection: EarlyDataReplayProtection::None,
+            reloader: None,
         }
     }
 
@@ -142,6 +162,84 @@ impl TlsAcceptor {
     }
 }
 
+#[cfg(feature = "tls")]
+impl TlsAcceptor {
+    fn reloader(&self) -> Result<&CertificateReloader, TlsError> {
+        self.reloader.as_deref().ok_or_else(|| {
+            TlsError::Configuration(
+                "acceptor was created from a raw ServerConfig and cannot reload certificates"
+                    .into(),
+            )
+        })
+    }
+
+    /// Metadata of the certificate currently served to new handshakes.
+    ///
+    /// Suitable for readiness probes. Returns `None` for acceptors created
+    /// with [`TlsAcceptor::new`].
+    #[must_use]
+    pub fn certificate_metadata(&self) -> Option<CertificateMetadata> {
+        self.reloader.as_ref().map(|reloader| reloader.metadata())
+    }
+
+    /// Returns the validity left if the served certificate expires within
+    /// the warning window (see
+    /// [`TlsAcceptorBuilder::expiry_warning_window`]), and `None` otherwise.
+    ///
+    /// The first positive check for a given certificate emits
+    /// [`CertificateEvent::ExpiryWarning`].
+    pub fn check_certificate_expiry(&self) -> Option<Duration> {
+        self.reloader.as_ref()?.check_expiry()
+    }
+
+    /// Replaces the served certificate with `chain` and `key`.
+    ///
+    /// The pair is validated first: the chain must parse and pass the
+    /// builder's chain checks (including the validity window unless strict
+    /// validation was disabled), and the key must match the leaf. On
+    /// success, later handshakes use the new certificate while handshakes in
+    /// flight and established connections keep the old one, and
+    /// [`CertificateEvent::Reloaded`] is emitted. On failure the old
+    /// certificate stays in service and [`CertificateEvent::ReloadRejected`]
+    /// is emitted.
+    pub fn reload_certificates_with(
+        &self,
+        chain: &CertificateChain,
+        key: &PrivateKey,
+    ) -> Result<CertificateMetadata, TlsError> {
+        self.reloader()?.reload_with(chain, key)
+    }
+
+    /// Reloads the certificate chain and key from the PEM files the acceptor
+    /// was built from (see [`TlsAcceptorBuilder::from_pem_files`]), with the
+    /// same validation as [`Self::reload_certificates_with`].
+    pub fn reload_certificates(&self) -> Result<CertificateMetadata, TlsError> {
+        self.reloader()?.reload_from_files()
+    }
+
+    /// Like [`Self::reload_certificates`], but only if either PEM file
+    /// changed since the last attempt. Returns `Ok(None)` when nothing
+    /// changed.
+    pub fn reload_certificates_if_changed(&self) -> Result<Option<CertificateMetadata>, TlsError> {
+        self.reloader()?.reload_if_changed()
+    }
+
+    /// Polls the PEM files every `interval`, reloading when they change and
+    /// checking for upcoming expiry, until `cx` is cancelled.
+    ///
+    /// Failed reloads are reported as events and do not stop the watcher.
+    pub async fn watch_certificates(&self, cx: &Cx, interval: Duration) -> Result<(), TlsError> {
+        let reloader = self.reloader()?;
+        reloader.require_paths()?;
+        while cx.checkpoint().is_ok() {
+            let _ = reloader.reload_if_changed();
+            reloader.check_expiry();
+            crate::time::sleep(super::timeout_now(), interval).await;
+        }
+        Ok(())
+    }
+}
+
 impl EarlyDataReplayProtection {
     /// Check if an HTTP method is safe for 0-RTT with this protection strategy.
     ///
@@ -480,6 +578,20 @@ pub struct TlsAcceptorBuilder {
     /// acknowledged. Production deployments MUST specify a replay
     /// protection strategy when enabling 0-RTT.
     early_data_replay_protection: EarlyDataReplayProtection,
+    /// PEM files the chain and key were loaded from, kept so the acceptor
+    /// can reload them. Set by [`Self::from_pem_files`].
+    #[cfg(feature = "tls")]
+    pem_paths: Option<(PathBuf, PathBuf)>,
+    /// Clock that judges certificate validity, read as Unix time. See
+    /// [`Self::certificate_clock`].
+    #[cfg(feature = "tls")]
+    certificate_clock: CertificateClock,
+    /// See [`Self::expiry_warning_window`].
+    #[cfg(feature = "tls")]
+    expiry_warning_window: Duration,
+    /// See [`Self::on_certificate_event`].
+    #[cfg(feature = "tls")]
+    certificate_events: Option<CertificateEventListener>,
 }
 
 impl TlsAcceptorBuilder {
@@ -490,19 +602,20 @@ impl TlsAcceptorBuilder {
     /// deployment of expired or invalid certificate chains.
     #[cfg(feature = "tls")]
     fn validate_certificate_chain(chain: &CertificateChain) -> Result<(), TlsError> {
+        Self::validate_certificate_chain_at(chain, CertificateClock::default().now())
+    }
+
+    /// [`Self::validate_certificate_chain`] against an explicit `now`, read
+    /// as time since the Unix epoch.
+    #[cfg(feature = "tls")]
+    pub(super) fn validate_certificate_chain_at(
+        chain: &CertificateChain,
+        now: Time,
+    ) -> Result<(), TlsError> {
         if chain.is_empty() {
             return Err(TlsError::Configuration("certificate chain is empty".into()));
         }
 
-        // Get current time for expiration checking
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|_| {
-                TlsError::Configuration(
-                    "failed to get current time for certificate validation".into(),
-                )
-            })?;
-
         // Validate each certificate in the chain
         for (i, cert) in chain.clone().into_iter().enumerate() {
             let cert_der = cert.as_der();
@@ -615,6 +728,14 @@ impl TlsAcceptorBuilder {
             // Operators must explicitly specify protection strategy
             // when enabling 0-RTT.
             early_data_replay_protection: EarlyDataReplayProtection::None,
+            #[cfg(feature = "tls")]
+            pem_paths: None,
+            #[cfg(feature = "tls")]
+            certificate_clock: CertificateClock::default(),
+            #[cfg(feature = "tls")]
+            expiry_warning_window: DEFAULT_EXPIRY_WARNING_WINDOW,
+            #[cfg(feature = "tls")]
+            certificate_events: None,
         }
     }
 
@@ -841,13 +962,59 @@ impl TlsAcceptorBuilder {
     }
 
     /// Create a builder by loading certificate chain and key from PEM files.
+    ///
+    /// The paths are remembered so the built acceptor can reload them (see
+    /// [`TlsAcceptor::reload_certificates`]).
     pub fn from_pem_files(
         cert_path: impl AsRef<Path>,
         key_path: impl AsRef<Path>,
     ) -> Result<Self, TlsError> {
-        let chain = CertificateChain::from_pem_file(cert_path)?;
-        let key = PrivateKey::from_pem_file(key_path)?;
-        Ok(Self::new(chain, key))
+        let chain = CertificateChain::from_pem_file(cert_path.as_ref())?;
+        let key = PrivateKey::from_pem_file(key_path.as_ref())?;
+        let builder = Self::new(chain, key);
+        #[cfg(feature = "tls")]
+        let builder = Self {
+            pem_paths: Some((
+                cert_path.as_ref().to_path_buf(),
+                key_path.as_ref().to_path_buf(),
+            )),
+            ..builder
+        };
+        Ok(builder)
+    }
+
+    /// Judge certificate validity against `clock` instead of the system
+    /// clock. The clock's [`Time`] is read as time since the Unix epoch.
+    ///
+    /// Applies to build-time validation, reloads and expiry checks.
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn certificate_clock(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.certificate_clock = CertificateClock::new(clock);
+        self
+    }
+
+    /// Warn when the served certificate expires within `window`
+    /// (default [`DEFAULT_EXPIRY_WARNING_WINDOW`]).
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn expiry_warning_window(mut self, window: Duration) -> Self {
+        self.expiry_warning_window = window;
+        self
+    }
+
+    /// Receive [`CertificateEvent`]s for reloads and expiry warnings.
+    ///
+    /// The listener runs on the thread that triggered the event and must
+    /// not reload certificates itself.
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn on_certificate_event(
+        mut self,
+        listener: impl Fn(&CertificateEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.certificate_events = Some(CertificateEventListener::new(listener));
+        self
     }
 
     /// Set client authentication mode.
@@ -994,7 +1161,7 @@ impl TlsAcceptorBuilder {
         // prevents deployment of expired certificates that would fail
         // in production but might not be caught during configuration.
         if self.strict_cert_validation {
-            Self::validate_certificate_chain(&self.cert_chain)?;
+            Self::validate_certificate_chain_at(&self.cert_chain, self.certificate_clock.now())?;
         }
 
         // SECURITY: br-asupersync-ycuuwy — validate 0-RTT replay protection
@@ -1126,7 +1293,8 @@ impl TlsAcceptorBuilder {
         // narrower protocol range than the rustls safe defaults (e.g.,
         // TLS 1.3 only to eliminate downgrade-attack surface and TLS
         // 1.2 cipher-suite negotiation pitfalls).
-        let builder = ServerConfig::builder_with_provider(Arc::new(default_provider()));
+        let provider = Arc::new(default_provider());
+        let builder = ServerConfig::builder_with_provider(Arc::clone(&provider));
         let builder = if self.min_protocol.is_some() || self.max_protocol.is_some() {
             // Convert protocol versions to the wire ordinals so the
             // range comparison works regardless of the
@@ -1198,9 +1366,24 @@ impl TlsAcceptorBuilder {
             }
         };
 
-        let mut config = builder
-            .with_single_cert(self.cert_chain.into_inner(), self.key.clone_inner())
-            .map_err(|e| TlsError::Configuration(e.to_string()))?;
+        // The certificate is served through a resolver so it can be
+        // swapped later without rebuilding the config.
+        let reloader = CertificateReloader::new(
+            &self.cert_chain,
+            &self.key,
+            provider,
+            ReloadOptions {
+                paths: self.pem_paths,
+                policy: ChainPolicy {
+                    strict: self.strict_cert_validation,
+                    require_full_chain: self.require_full_chain,
+                },
+                clock: self.certificate_clock,
+                expiry_warning: self.expiry_warning_window,
+                listener: self.certificate_events,
+            },
+        )?;
+        let mut config = builder.with_cert_resolver(reloader.resolver());
 
         // Set ALPN if specified
         if !self.alpn_protocols.is_empty() {
@@ -1234,6 +1417,7 @@ impl TlsAcceptorBuilder {
             require_sni: self.require_sni,
             sni_alpn_allow_list: self.sni_alpn_allow_list.map(Arc::new),
             early_data_replay_protection: self.early_data_replay_protection,
+            reloader: Some(Arc::new(reloader)),
         })
     }
 
@@ -2782,4 +2966,348 @@ SrXuVI5uunTgPWuOtJOP+KM=
                 .is_err()
         );
     }
+
+    // ── Certificate hot-reload ───────────────────────────────────────
+
+    #[cfg(feature = "tls")]
+    const FIXTURE_CERT_PEM: &[u8] = include_bytes!("../../tests/fixtures/tls/server.crt");
+    #[cfg(feature = "tls")]
+    const FIXTURE_KEY_PEM: &[u8] = include_bytes!("../../tests/fixtures/tls/server.key");
+
+    #[cfg(feature = "tls")]
+    fn leaf_der(pem: &[u8]) -> Vec<u8> {
+        Certificate::from_pem(pem).unwrap()[0].as_der().to_vec()
+    }
+
+    #[cfg(feature = "tls")]
+    fn event_log() -> (
+        Arc<parking_lot::Mutex<Vec<CertificateEvent>>>,
+        impl Fn(&CertificateEvent) + Send + Sync + 'static,
+    ) {
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        (events, move |event: &CertificateEvent| {
+            sink.lock().push(event.clone());
+        })
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_certificate_reload_under_concurrent_handshakes() {
+        use crate::net::tcp::VirtualTcpStream;
+        use crate::test_utils::run_test_with_cx;
+        use futures_lite::future::zip;
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let acceptor = TlsAcceptorBuilder::new(
+            CertificateChain::from_pem(TEST_CERT_PEM).unwrap(),
+            PrivateKey::from_pem(TEST_KEY_PEM).unwrap(),
+        )
+        .build()
+        .unwrap();
+        let connector = crate::tls::TlsConnectorBuilder::new()
+            .add_root_certificates(Certificate::from_pem(TEST_CERT_PEM).unwrap())
+            .add_root_certificates(Certificate::from_pem(FIXTURE_CERT_PEM).unwrap())
+            .build()
+            .unwrap();
+        let leaves = Arc::new([leaf_der(TEST_CERT_PEM), leaf_der(FIXTURE_CERT_PEM)]);
+        let served = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let workers: Vec<_> = (0..4u16)
+            .map(|worker| {
+                let acceptor = acceptor.clone();
+                let connector = connector.clone();
+                let leaves = Arc::clone(&leaves);
+                let served = Arc::clone(&served);
+                let stop = Arc::clone(&stop);
+                std::thread::spawn(move || {
+                    run_test_with_cx(|_cx| async move {
+                        let mut rounds = 0;
+                        while rounds < 4 || !stop.load(Ordering::Acquire) {
+                            let (client_io, server_io) = VirtualTcpStream::pair(
+                                format!("127.0.0.1:{}", 7000 + worker * 2).parse().unwrap(),
+                                format!("127.0.0.1:{}", 7001 + worker * 2).parse().unwrap(),
+                            );
+                            let (client_res, server_res) = zip(
+                                connector.connect("localhost", client_io),
+                                acceptor.accept(server_io),
+                            )
+                            .await;
+                            let client = client_res.expect("client handshake during reloads");
+                            server_res.expect("server handshake during reloads");
+                            let leaf = client.peer_leaf_certificate_der().expect("peer leaf");
+                            let index = leaves
+                                .iter()
+                                .position(|known| *known == leaf)
+                                .expect("server presented one of the two pairs");
+                            served[index].fetch_add(1, Ordering::Relaxed);
+                            rounds += 1;
+                        }
+                    });
+                })
+            })
+            .collect();
+
+        let pairs = [
+            (TEST_CERT_PEM, TEST_KEY_PEM),
+            (FIXTURE_CERT_PEM, FIXTURE_KEY_PEM),
+        ];
+        for swap in 1..=20 {
+            let (cert, key) = pairs[swap % 2];
+            acceptor
+                .reload_certificates_with(
+                    &CertificateChain::from_pem(cert).unwrap(),
+                    &PrivateKey::from_pem(key).unwrap(),
+                )
+                .expect("valid pair reloads");
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        stop.store(true, Ordering::Release);
+        for worker in workers {
+            worker.join().expect("handshake worker panicked");
+        }
+        let total = served[0].load(Ordering::Relaxed) + served[1].load(Ordering::Relaxed);
+        assert!(total >= 16, "every worker completes its handshakes");
+
+        // The last swap installed the test pair; new handshakes see it.
+        let final_leaf = Arc::new(parking_lot::Mutex::new(None));
+        let observed = Arc::clone(&final_leaf);
+        run_test_with_cx(|_cx| async move {
+            let (client_io, server_io) = VirtualTcpStream::pair(
+                "127.0.0.1:7100".parse().unwrap(),
+                "127.0.0.1:7101".parse().unwrap(),
+            );
+            let (client_res, _server_res) = zip(
+                connector.connect("localhost", client_io),
+                acceptor.accept(server_io),
+            )
+            .await;
+            *observed.lock() = client_res.unwrap().peer_leaf_certificate_der();
+        });
+        assert_eq!(final_leaf.lock().as_deref(), Some(leaves[0].as_slice()));
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_certificate_reload_rejects_mismatched_pair() {
+        let (events, listener) = event_log();
+        let acceptor = TlsAcceptorBuilder::new(
+            CertificateChain::from_pem(TEST_CERT_PEM).unwrap(),
+            PrivateKey::from_pem(TEST_KEY_PEM).unwrap(),
+        )
+        .on_certificate_event(listener)
+        .build()
+        .unwrap();
+        let before = acceptor.certificate_metadata().unwrap();
+
+        // Fixture certificate with the test key: the key does not match.
+        let err = acceptor
+            .reload_certificates_with(
+                &CertificateChain::from_pem(FIXTURE_CERT_PEM).unwrap(),
+                &PrivateKey::from_pem(TEST_KEY_PEM).unwrap(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, TlsError::Configuration(_)), "{err:?}");
+        assert_eq!(acceptor.certificate_metadata().unwrap(), before);
+
+        let err = acceptor
+            .reload_certificates_with(
+                &CertificateChain::new(),
+                &PrivateKey::from_pem(TEST_KEY_PEM).unwrap(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, TlsError::Configuration(_)), "{err:?}");
+        assert_eq!(acceptor.certificate_metadata().unwrap(), before);
+
+        let events = events.lock();
+        assert_eq!(events.len(), 2, "{events:?}");
+        for event in events.iter() {
+            match event {
+                CertificateEvent::ReloadRejected { current, .. } => assert_eq!(current, &before),
+                other => panic!("expected ReloadRejected, got {other:?}"),
+            }
+        }
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_certificate_expiry_warning_at_threshold() {
+        use crate::time::VirtualClock;
+
+        const DAY: u64 = 24 * 60 * 60;
+        // Fixture notAfter: Apr 17 15:35:49 2027 GMT.
+        const NOT_AFTER: u64 = 1_807_976_149;
+        let window = std::time::Duration::from_secs(7 * DAY);
+        let clock = Arc::new(VirtualClock::starting_at(Time::from_secs(
+            NOT_AFTER - 7 * DAY - 1,
+        )));
+        let (events, listener) = event_log();
+        let acceptor = TlsAcceptorBuilder::new(
+            CertificateChain::from_pem(FIXTURE_CERT_PEM).unwrap(),
+            PrivateKey::from_pem(FIXTURE_KEY_PEM).unwrap(),
+        )
+        .certificate_clock(clock.clone())
+        .expiry_warning_window(window)
+        .on_certificate_event(listener)
+        .build()
+        .unwrap();
+
+        assert_eq!(acceptor.check_certificate_expiry(), None);
+        assert!(events.lock().is_empty());
+
+        clock.advance(1_000_000_000);
+        assert_eq!(acceptor.check_certificate_expiry(), Some(window));
+        clock.advance(DAY * 1_000_000_000);
+        assert_eq!(
+            acceptor.check_certificate_expiry(),
+            Some(std::time::Duration::from_secs(6 * DAY))
+        );
+
+        let events = events.lock();
+        assert_eq!(events.len(), 1, "warning fires once per certificate");
+        match &events[0] {
+            CertificateEvent::ExpiryWarning { current, remaining } => {
+                assert_eq!(current.not_after, NOT_AFTER as i64);
+                assert_eq!(*remaining, window);
+            }
+            other => panic!("expected ExpiryWarning, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_certificate_metadata_tracks_pem_file_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("server.crt");
+        let key_path = dir.path().join("server.key");
+        std::fs::write(&cert_path, TEST_CERT_PEM).unwrap();
+        std::fs::write(&key_path, TEST_KEY_PEM).unwrap();
+
+        let (events, listener) = event_log();
+        let acceptor = TlsAcceptorBuilder::from_pem_files(&cert_path, &key_path)
+            .unwrap()
+            .on_certificate_event(listener)
+            .build()
+            .unwrap();
+        let first = acceptor.certificate_metadata().unwrap();
+        assert_eq!(first.serial, "10E6BFC599CBD9772599ED904AE782AC748C2E71");
+        assert_eq!(first.subject, "CN=localhost");
+        // notAfter: Feb 24 22:29:52 2036 GMT.
+        assert_eq!(first.not_after, 2_087_504_992);
+        assert_eq!(acceptor.reload_certificates_if_changed().unwrap(), None);
+
+        std::fs::write(&cert_path, FIXTURE_CERT_PEM).unwrap();
+        std::fs::write(&key_path, FIXTURE_KEY_PEM).unwrap();
+        let second = acceptor
+            .reload_certificates_if_changed()
+            .unwrap()
+            .expect("changed files are reloaded");
+        assert_eq!(second.serial, "319CCC27734AFF08AF115D00C23643364B2C6C4B");
+        assert_eq!(acceptor.certificate_metadata().unwrap(), second);
+        assert_eq!(acceptor.reload_certificates_if_changed().unwrap(), None);
+        assert_eq!(acceptor.reload_certificates().unwrap(), second);
+
+        assert_eq!(
+            events.lock()[0],
+            CertificateEvent::Reloaded {
+                previous: first,
+                current: second,
+            }
+        );
+
+        // Acceptors over a raw config have a fixed certificate.
+        let raw = TlsAcceptor::new((**acceptor.config()).clone());
+        assert!(raw.certificate_metadata().is_none());
+        assert!(raw.check_certificate_expiry().is_none());
+        assert!(matches!(
+            raw.reload_certificates(),
+            Err(TlsError::Configuration(_))
+        ));
+    }
+
+    #[cfg(feature = "tls")]
+    fn handshake_succeeds(connector: &crate::tls::TlsConnector, acceptor: &TlsAcceptor) -> bool {
+        use crate::net::tcp::VirtualTcpStream;
+        use crate::test_utils::run_test_with_cx;
+        use futures_lite::future::zip;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let succeeded = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&succeeded);
+        let connector = connector.clone();
+        let acceptor = acceptor.clone();
+        run_test_with_cx(|_cx| async move {
+            let (client_io, server_io) = VirtualTcpStream::pair(
+                "127.0.0.1:7200".parse().unwrap(),
+                "127.0.0.1:7201".parse().unwrap(),
+            );
+            let (client_res, server_res) = zip(
+                connector.connect("localhost", client_io),
+                acceptor.accept(server_io),
+            )
+            .await;
+            flag.store(client_res.is_ok() && server_res.is_ok(), Ordering::SeqCst);
+        });
+        succeeded.load(Ordering::SeqCst)
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_reloadable_roots_follow_pem_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots_path = dir.path().join("roots.pem");
+        std::fs::write(&roots_path, TEST_CERT_PEM).unwrap();
+
+        let (events, listener) = event_log();
+        let connector = crate::tls::TlsConnectorBuilder::new()
+            .reloadable_roots_from_pem_file(&roots_path)
+            .unwrap()
+            .on_certificate_event(listener)
+            .build()
+            .unwrap();
+        let acceptor = TlsAcceptorBuilder::new(
+            CertificateChain::from_pem(FIXTURE_CERT_PEM).unwrap(),
+            PrivateKey::from_pem(FIXTURE_KEY_PEM).unwrap(),
+        )
+        .build()
+        .unwrap();
+
+        assert!(!handshake_succeeds(&connector, &acceptor));
+        assert_eq!(
+            connector.reload_root_certificates_if_changed().unwrap(),
+            None
+        );
+
+        std::fs::write(&roots_path, FIXTURE_CERT_PEM).unwrap();
+        assert_eq!(
+            connector.reload_root_certificates_if_changed().unwrap(),
+            Some(1)
+        );
+        assert!(handshake_succeeds(&connector, &acceptor));
+
+        // An empty store is rejected and the previous roots stay in use.
+        assert!(
+            connector
+                .reload_root_certificates_with(RootCertStore::empty())
+                .is_err()
+        );
+        assert!(handshake_succeeds(&connector, &acceptor));
+
+        let events = events.lock();
+        assert_eq!(events[0], CertificateEvent::RootsReloaded { roots: 1 });
+        assert!(
+            matches!(events[1], CertificateEvent::RootsReloadRejected { .. }),
+            "{events:?}"
+        );
+
+        let fixed = crate::tls::TlsConnectorBuilder::new()
+            .add_root_certificates(Certificate::from_pem(FIXTURE_CERT_PEM).unwrap())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            fixed.reload_root_certificates_with(RootCertStore::empty()),
+            Err(TlsError::Configuration(_))
+        ));
+    }
 }
@@ -4,8 +4,12 @@
 //! TLS connections from the client side.
 
 use super::error::TlsError;
+#[cfg(feature = "tls")]
+use super::reload::{CertificateEvent, CertificateEventListener, RootReloader};
 use super::stream::TlsStream;
 use super::types::{Certificate, CertificateChain, CertificatePinSet, PrivateKey, RootCertStore};
+#[cfg(feature = "tls")]
+use crate::cx::Cx;
 use crate::io::{AsyncRead, AsyncWrite};
 
 #[cfg(feature = "tls")]
@@ -17,7 +21,11 @@ use rustls::pki_types::ServerName;
 
 #[cfg(feature = "tls")]
 use std::future::poll_fn;
+#[cfg(feature = "tls")]
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+#[cfg(feature = "tls")]
+use std::time::Duration;
 
 #[cfg(not(feature = "tls"))]
 const TLS_FEATURE_HINT: &str = "rebuild with --features tls";
@@ -63,6 +71,10 @@ pub struct TlsConnector {
     /// aborts the connection. `None` (the default) skips pinning
     /// — webpki / native roots remain the only check.
     pin_set: Option<Arc<CertificatePinSet>>,
+    /// Replaces the trust store at runtime. `None` unless built with
+    /// [`TlsConnectorBuilder::reloadable_roots`].
+    #[cfg(feature = "tls")]
+    root_reloader: Option<Arc<RootReloader>>,
     #[cfg(not(feature = "tls"))]
     _marker: std::marker::PhantomData<()>,
 }
@@ -76,6 +88,7 @@ impl TlsConnector {
             handshake_timeout: None,
             alpn_required: false,
             pin_set: None,
+            root_reloader: None,
         }
     }
 
@@ -234,6 +247,59 @@ impl TlsConnector {
     }
 }
 
+#[cfg(feature = "tls")]
+impl TlsConnector {
+    fn root_reloader(&self) -> Result<&RootReloader, TlsError> {
+        self.root_reloader.as_deref().ok_or_else(|| {
+            TlsError::Configuration(
+                "connector was not built with reloadable_roots() and cannot reload roots".into(),
+            )
+        })
+    }
+
+    /// Replaces the trust store used to verify servers in later handshakes.
+    ///
+    /// Handshakes in flight and established connections are unaffected.
+    /// Returns the number of trust anchors now in use and emits
+    /// [`CertificateEvent::RootsReloaded`]; an empty store or one the
+    /// verifier rejects leaves the previous store in use and emits
+    /// [`CertificateEvent::RootsReloadRejected`].
+    pub fn reload_root_certificates_with(&self, roots: RootCertStore) -> Result<usize, TlsError> {
+        self.root_reloader()?.reload_with(roots)
+    }
+
+    /// Reloads the trust store from the PEM file given to
+    /// [`TlsConnectorBuilder::reloadable_roots_from_pem_file`].
+    pub fn reload_root_certificates(&self) -> Result<usize, TlsError> {
+        self.root_reloader()?.reload_from_file()
+    }
+
+    /// Like [`Self::reload_root_certificates`], but only if the PEM file
+    /// changed since the last attempt. Returns `Ok(None)` when nothing
+    /// changed.
+    pub fn reload_root_certificates_if_changed(&self) -> Result<Option<usize>, TlsError> {
+        self.root_reloader()?.reload_if_changed()
+    }
+
+    /// Polls the root PEM file every `interval`, reloading it when it
+    /// changes, until `cx` is cancelled.
+    ///
+    /// Failed reloads are reported as events and do not stop the watcher.
+    pub async fn watch_root_certificates(
+        &self,
+        cx: &Cx,
+        interval: Duration,
+    ) -> Result<(), TlsError> {
+        let reloader = self.root_reloader()?;
+        reloader.require_path()?;
+        while cx.checkpoint().is_ok() {
+            let _ = reloader.reload_if_changed();
+            crate::time::sleep(super::timeout_now(), interval).await;
+        }
+        Ok(())
+    }
+}
+
 impl std::fmt::Debug for TlsConnector {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TlsConnector").finish_non_exhaustive()
@@ -303,6 +369,17 @@ pub struct TlsConnectorBuilder {
     /// [`Self::with_strict_ca_validation`]; automatically set as a
     /// side effect of [`Self::enable_env_cert_loading`].
     validate_ca_constraints: bool,
+    /// When `true`, `build()` verifies servers through a root store that
+    /// can be replaced later. See [`Self::reloadable_roots`].
+    #[cfg(feature = "tls")]
+    reloadable_roots: bool,
+    /// PEM file the reloadable roots came from. See
+    /// [`Self::reloadable_roots_from_pem_file`].
+    #[cfg(feature = "tls")]
+    root_pem_path: Option<PathBuf>,
+    /// See [`Self::on_certificate_event`].
+    #[cfg(feature = "tls")]
+    certificate_events: Option<CertificateEventListener>,
 }
 
 impl TlsConnectorBuilder {
@@ -345,9 +422,56 @@ impl TlsConnectorBuilder {
             // also sets it as a side effect because env-loaded certs
             // are unconditionally CA-gated by br-asupersync-0owoem.
             validate_ca_constraints: false,
+            #[cfg(feature = "tls")]
+            reloadable_roots: false,
+            #[cfg(feature = "tls")]
+            root_pem_path: None,
+            #[cfg(feature = "tls")]
+            certificate_events: None,
         }
     }
 
+    /// Verify servers through a root store that can be replaced after the
+    /// connector is built (see [`TlsConnector::reload_root_certificates_with`]).
+    ///
+    /// The initial store is the one configured on this builder; any CRLs
+    /// stay attached across reloads.
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn reloadable_roots(mut self) -> Self {
+        self.reloadable_roots = true;
+        self
+    }
+
+    /// Add the trust anchors in the PEM file at `path` and make them
+    /// reloadable from that file (see
+    /// [`TlsConnector::reload_root_certificates`] and
+    /// [`TlsConnector::watch_root_certificates`]).
+    #[cfg(feature = "tls")]
+    pub fn reloadable_roots_from_pem_file(
+        mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, TlsError> {
+        self.root_certs.add_pem_file(path.as_ref())?;
+        self.root_pem_path = Some(path.as_ref().to_path_buf());
+        self.reloadable_roots = true;
+        Ok(self)
+    }
+
+    /// Receive [`CertificateEvent`]s for root-store reloads.
+    ///
+    /// The listener runs on the thread that triggered the event and must
+    /// not reload roots itself.
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn on_certificate_event(
+        mut self,
+        listener: impl Fn(&CertificateEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.certificate_events = Some(CertificateEventListener::new(listener));
+        self
+    }
+
     /// Attach a [`CertificatePinSet`] for post-handshake leaf-cert
     /// validation.
     ///
@@ -1062,7 +1186,8 @@ impl TlsConnectorBuilder {
         }
 
         // Create the config builder with the crypto provider and protocol versions.
-        let builder = ClientConfig::builder_with_provider(Arc::new(default_provider()));
+        let provider = Arc::new(default_provider());
+        let builder = ClientConfig::builder_with_provider(Arc::clone(&provider));
         let builder = if self.min_protocol.is_some() || self.max_protocol.is_some() {
             // Convert protocol versions to ordinals for comparison.
             // TLS 1.2 = 0x0303, TLS 1.3 = 0x0304
@@ -1118,32 +1243,48 @@ impl TlsConnectorBuilder {
         // verifier still uses the same trust roots; CRLs apply per-
         // issuer at validation time. With no CRLs, the rustls default
         // verifier path is used (existing behavior preserved).
-        let roots = self.root_certs.into_inner();
-        let builder = if self.crl_pems.is_empty() {
-            builder.with_root_certificates(roots)
-        } else {
-            let mut crl_ders: Vec<rustls::pki_types::CertificateRevocationListDer<'static>> =
-                Vec::new();
-            for pem in &self.crl_pems {
-                let mut reader = std::io::BufReader::new(&pem[..]);
-                let der_iter = rustls_pemfile::crls(&mut reader);
-                for der in der_iter {
-                    let der = der.map_err(|e| {
-                        TlsError::Configuration(format!("CRL PEM parse error: {e}"))
-                    })?;
-                    crl_ders.push(der);
-                }
-            }
-            if crl_ders.is_empty() {
-                return Err(TlsError::Configuration(
-                    "with_crl_pem called but no CRL blocks were parsed from the supplied PEM(s)"
-                        .into(),
-                ));
+        let mut crl_ders: Vec<rustls::pki_types::CertificateRevocationListDer<'static>> =
+            Vec::new();
+        for pem in &self.crl_pems {
+            let mut reader = std::io::BufReader::new(&pem[..]);
+            let der_iter = rustls_pemfile::crls(&mut reader);
+            for der in der_iter {
+                let der =
+                    der.map_err(|e| TlsError::Configuration(format!("CRL PEM parse error: {e}")))?;
+                crl_ders.push(der);
             }
-            let verifier = rustls::client::WebPkiServerVerifier::builder(Arc::new(roots))
-                .with_crls(crl_ders)
-                .build()
-                .map_err(|e| TlsError::Configuration(format!("CRL verifier build: {e}")))?;
+        }
+        if !self.crl_pems.is_empty() && crl_ders.is_empty() {
+            return Err(TlsError::Configuration(
+                "with_crl_pem called but no CRL blocks were parsed from the supplied PEM(s)".into(),
+            ));
+        }
+
+        let mut root_reloader = None;
+        let builder = if self.reloadable_roots {
+            // Reloadable roots go through a verifier whose inner webpki
+            // verifier (CRLs included) is rebuilt on every reload.
+            let reloader = RootReloader::new(
+                self.root_certs,
+                crl_ders,
+                provider,
+                self.root_pem_path,
+                self.certificate_events,
+            )?;
+            let verifier = reloader.verifier();
+            root_reloader = Some(Arc::new(reloader));
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(verifier)
+        } else if crl_ders.is_empty() {
+            builder.with_root_certificates(self.root_certs.into_inner())
+        } else {
+            let verifier = rustls::client::WebPkiServerVerifier::builder(Arc::new(
+                self.root_certs.into_inner(),
+            ))
+            .with_crls(crl_ders)
+            .build()
+            .map_err(|e| TlsError::Configuration(format!("CRL verifier build: {e}")))?;
             // The dangerous() name reflects that callers can plug in
             // an arbitrary verifier — here we plug in webpki's own
             // verifier with CRLs attached, which is *strictly more*
@@ -1199,6 +1340,7 @@ impl TlsConnectorBuilder {
             handshake_timeout: self.handshake_timeout,
             alpn_required: self.alpn_required,
             pin_set: self.pin_set.map(Arc::new),
+            root_reloader,
         })
     }
 
@@ -28,6 +28,12 @@
 //! let tls_stream = connector.connect("example.com", tcp_stream).await?;
 //! ```
 //!
+//! # Certificate Reload
+//!
+//! With the `tls` feature, acceptors built by [`TlsAcceptorBuilder`] can swap
+//! their certificate at runtime and connectors built with `reloadable_roots()`
+//! their root store; outcomes are reported as `CertificateEvent`s.
+//!
 //! # Cancel-Safety
 //!
 //! TLS handshake operations are NOT cancel-safe. If cancelled mid-handshake,
@@ -43,6 +49,8 @@ use crate::types::Time;
 mod acceptor;
 mod connector;
 mod error;
+#[cfg(feature = "tls")]
+mod reload;
 mod stream;
 mod types;
 
@@ -60,6 +68,8 @@ pub use acceptor::EarlyDataReplayProtection;
 pub use acceptor::{ClientAuth, TlsAcceptor, TlsAcceptorBuilder};
 pub use connector::{TlsConnector, TlsConnectorBuilder};
 pub use error::TlsError;
+#[cfg(feature = "tls")]
+pub use reload::{CertificateEvent, CertificateMetadata, DEFAULT_EXPIRY_WARNING_WINDOW};
 pub use stream::TlsStream;
 pub use types::{
     Certificate, CertificateChain, CertificatePin, CertificatePinSet, PrivateKey, RootCertStore,
@@ -0,0 +1,737 @@
+//! Certificate hot-reload.
+//!
+//! An acceptor built by [`TlsAcceptorBuilder`] serves its certificate through
+//! a resolver whose key can be replaced while the acceptor is in use.
+//! Replacement material is validated before it is served: the chain must
+//! parse, sit inside its validity window and match the private key. The swap
+//! itself is a single pointer store, so handshakes that already picked a
+//! certificate finish with it and established connections are untouched.
+//! Rejected material leaves the served certificate in place.
+//!
+//! Connectors built with
+//! [`TlsConnectorBuilder::reloadable_roots`](super::TlsConnectorBuilder::reloadable_roots)
+//! verify servers through a verifier whose root store can be replaced the
+//! same way.
+//!
+//! Every reload outcome and expiry warning is reported as a
+//! [`CertificateEvent`] to the configured listener, and to `tracing` under
+//! `tracing-integration`.
+
+use super::acceptor::TlsAcceptorBuilder;
+use super::error::TlsError;
+use super::types::{CertificateChain, PrivateKey, RootCertStore};
+use crate::time::TimeSource;
+use crate::types::Time;
+use parking_lot::{Mutex, RwLock};
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, CertificateRevocationListDer, ServerName, UnixTime};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Window before `notAfter` in which [`CertificateEvent::ExpiryWarning`]
+/// fires unless the acceptor is configured otherwise.
+pub const DEFAULT_EXPIRY_WARNING_WINDOW: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Identity and validity window of a served certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateMetadata {
+    /// Serial number as upper-case hex without separators.
+    pub serial: String,
+    /// Subject distinguished name.
+    pub subject: String,
+    /// Start of the validity window, in seconds since the Unix epoch.
+    pub not_before: i64,
+    /// End of the validity window (`notAfter`), in seconds since the Unix
+    /// epoch.
+    pub not_after: i64,
+}
+
+impl CertificateMetadata {
+    /// Parses the metadata of a DER-encoded certificate.
+    pub(crate) fn from_der(der: &[u8]) -> Result<Self, TlsError> {
+        let (_, cert) = x509_parser::parse_x509_certificate(der)
+            .map_err(|e| TlsError::Certificate(format!("failed to parse leaf certificate: {e}")))?;
+
+        // DER integers carry a leading zero byte when the high bit is set;
+        // drop it so the serial reads the way `openssl x509 -serial` prints it.
+        let raw = cert.raw_serial();
+        let start = raw
+            .iter()
+            .position(|byte| *byte != 0)
+            .unwrap_or_else(|| raw.len().saturating_sub(1));
+        let mut serial = String::with_capacity(raw.len() * 2);
+        for byte in &raw[start..] {
+            let _ = write!(serial, "{byte:02X}");
+        }
+
+        let validity = cert.validity();
+        Ok(Self {
+            serial,
+            subject: cert.subject().to_string(),
+            not_before: validity.not_before.timestamp(),
+            not_after: validity.not_after.timestamp(),
+        })
+    }
+
+    /// Returns `notAfter` as wall-clock time.
+    #[must_use]
+    pub fn not_after_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(u64::try_from(self.not_after).unwrap_or(0))
+    }
+
+    /// Returns the validity left at `now`, read as time since the Unix
+    /// epoch, or `None` once the certificate has expired.
+    #[must_use]
+    pub fn remaining_validity(&self, now: Time) -> Option<Duration> {
+        let not_after = u64::try_from(self.not_after)
+            .ok()?
+            .saturating_mul(1_000_000_000);
+        not_after
+            .checked_sub(now.as_nanos())
+            .map(Duration::from_nanos)
+    }
+
+    /// Returns true if the certificate expires within `window` of `now`, or
+    /// has already expired.
+    #[must_use]
+    pub fn expires_within(&self, now: Time, window: Duration) -> bool {
+        self.remaining_validity(now)
+            .is_none_or(|remaining| remaining <= window)
+    }
+}
+
+/// Outcome of a certificate or root-store reload, or of an expiry check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateEvent {
+    /// The acceptor now serves `current` to new handshakes.
+    Reloaded {
+        /// Certificate served before the swap.
+        previous: CertificateMetadata,
+        /// Certificate served from now on.
+        current: CertificateMetadata,
+    },
+    /// Replacement material failed validation; `current` is still served.
+    ReloadRejected {
+        /// Why the replacement was rejected.
+        reason: String,
+        /// Certificate that remains in service.
+        current: CertificateMetadata,
+    },
+    /// The served certificate expires within the warning window. Emitted
+    /// once per served certificate.
+    ExpiryWarning {
+        /// Certificate that is about to expire.
+        current: CertificateMetadata,
+        /// Validity left when the warning fired.
+        remaining: Duration,
+    },
+    /// The connector now verifies servers against a new root store.
+    RootsReloaded {
+        /// Number of trust anchors in the new store.
+        roots: usize,
+    },
+    /// A replacement root store was rejected; the previous one stays in use.
+    RootsReloadRejected {
+        /// Why the replacement was rejected.
+        reason: String,
+    },
+}
+
+/// Callback receiving [`CertificateEvent`]s.
+#[derive(Clone)]
+pub(crate) struct CertificateEventListener(Arc<dyn Fn(&CertificateEvent) + Send + Sync>);
+
+impl CertificateEventListener {
+    pub(crate) fn new(listener: impl Fn(&CertificateEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(listener))
+    }
+}
+
+impl std::fmt::Debug for CertificateEventListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateEventListener")
+            .finish_non_exhaustive()
+    }
+}
+
+fn emit(listener: Option<&CertificateEventListener>, event: &CertificateEvent) {
+    #[cfg(feature = "tracing-integration")]
+    match event {
+        CertificateEvent::Reloaded { previous, current } => tracing::info!(
+            serial = %current.serial,
+            not_after = current.not_after,
+            previous_serial = %previous.serial,
+            "TLS certificate reloaded"
+        ),
+        CertificateEvent::ReloadRejected { reason, current } => tracing::error!(
+            reason = %reason,
+            serial = %current.serial,
+            not_after = current.not_after,
+            "TLS certificate reload rejected; keeping the current certificate"
+        ),
+        CertificateEvent::ExpiryWarning { current, remaining } => tracing::warn!(
+            serial = %current.serial,
+            not_after = current.not_after,
+            remaining_secs = remaining.as_secs(),
+            "TLS certificate expires soon"
+        ),
+        CertificateEvent::RootsReloaded { roots } => {
+            tracing::info!(roots = *roots, "TLS root certificates reloaded");
+        }
+        CertificateEvent::RootsReloadRejected { reason } => tracing::error!(
+            reason = %reason,
+            "TLS root certificate reload rejected; keeping the current roots"
+        ),
+    }
+    if let Some(listener) = listener {
+        (listener.0)(event);
+    }
+}
+
+/// Clock that judges certificate validity. Its [`Time`] is read as time
+/// since the Unix epoch.
+#[derive(Clone)]
+pub(crate) struct CertificateClock(Arc<dyn TimeSource>);
+
+impl CertificateClock {
+    pub(crate) const fn new(source: Arc<dyn TimeSource>) -> Self {
+        Self(source)
+    }
+
+    pub(crate) fn now(&self) -> Time {
+        self.0.now()
+    }
+}
+
+impl Default for CertificateClock {
+    fn default() -> Self {
+        Self(Arc::new(UnixWallClock))
+    }
+}
+
+impl std::fmt::Debug for CertificateClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CertificateClock")
+            .field(&self.now())
+            .finish()
+    }
+}
+
+struct UnixWallClock;
+
+impl TimeSource for UnixWallClock {
+    fn now(&self) -> Time {
+        Time::from_millis(crate::time::unix_time_millis())
+    }
+}
+
+/// Change detector for a watched file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        std::fs::metadata(path).ok().map(|meta| Self {
+            modified: meta.modified().ok(),
+            len: meta.len(),
+        })
+    }
+}
+
+/// Chain checks applied to replacement certificates, mirroring the
+/// builder's.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChainPolicy {
+    pub(crate) strict: bool,
+    pub(crate) require_full_chain: bool,
+}
+
+/// Everything a [`CertificateReloader`] needs besides the initial key.
+#[derive(Debug)]
+pub(crate) struct ReloadOptions {
+    pub(crate) paths: Option<(PathBuf, PathBuf)>,
+    pub(crate) policy: ChainPolicy,
+    pub(crate) clock: CertificateClock,
+    pub(crate) expiry_warning: Duration,
+    pub(crate) listener: Option<CertificateEventListener>,
+}
+
+#[derive(Debug)]
+struct Served {
+    key: Arc<CertifiedKey>,
+    metadata: CertificateMetadata,
+}
+
+impl Served {
+    fn new(
+        chain: &CertificateChain,
+        key: &PrivateKey,
+        provider: &CryptoProvider,
+    ) -> Result<Self, TlsError> {
+        let chain = chain.clone().into_inner();
+        let metadata = CertificateMetadata::from_der(
+            chain
+                .first()
+                .ok_or_else(|| TlsError::Configuration("certificate chain is empty".into()))?,
+        )?;
+        // `from_der` also checks that the key belongs to the leaf.
+        let key = CertifiedKey::from_der(chain, key.clone_inner(), provider)
+            .map_err(|e| TlsError::Configuration(format!("certificate/key pair rejected: {e}")))?;
+        Ok(Self {
+            key: Arc::new(key),
+            metadata,
+        })
+    }
+}
+
+/// Server certificate resolver whose key can be swapped at runtime.
+#[derive(Debug)]
+pub(crate) struct ReloadableCertResolver {
+    served: RwLock<Arc<Served>>,
+}
+
+impl ReloadableCertResolver {
+    fn current(&self) -> Arc<Served> {
+        Arc::clone(&self.served.read())
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.current().key))
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReloadState {
+    stamps: Option<[Option<FileStamp>; 2]>,
+    warned_serial: Option<String>,
+}
+
+/// Validates replacement certificates and swaps them into the acceptor's
+/// resolver.
+#[derive(Debug)]
+pub(crate) struct CertificateReloader {
+    resolver: Arc<ReloadableCertResolver>,
+    provider: Arc<CryptoProvider>,
+    options: ReloadOptions,
+    /// Serializes reloads; holds the file stamps last acted on.
+    state: Mutex<ReloadState>,
+}
+
+impl CertificateReloader {
+    /// Wraps an already validated chain and key.
+    pub(crate) fn new(
+        chain: &CertificateChain,
+        key: &PrivateKey,
+        provider: Arc<CryptoProvider>,
+        options: ReloadOptions,
+    ) -> Result<Self, TlsError> {
+        let served = Served::new(chain, key, &provider)?;
+        let stamps = options
+            .paths
+            .as_ref()
+            .map(|(cert, key)| [FileStamp::of(cert), FileStamp::of(key)]);
+        Ok(Self {
+            resolver: Arc::new(ReloadableCertResolver {
+                served: RwLock::new(Arc::new(served)),
+            }),
+            provider,
+            options,
+            state: Mutex::new(ReloadState {
+                stamps,
+                warned_serial: None,
+            }),
+        })
+    }
+
+    pub(crate) fn resolver(&self) -> Arc<ReloadableCertResolver> {
+        Arc::clone(&self.resolver)
+    }
+
+    pub(crate) fn metadata(&self) -> CertificateMetadata {
+        self.resolver.current().metadata.clone()
+    }
+
+    pub(crate) fn require_paths(&self) -> Result<(&Path, &Path), TlsError> {
+        self.options
+            .paths
+            .as_ref()
+            .map(|(cert, key)| (cert.as_path(), key.as_path()))
+            .ok_or_else(|| {
+                TlsError::Configuration(
+                    "acceptor was not built from PEM files; use reload_certificates_with".into(),
+                )
+            })
+    }
+
+    pub(crate) fn reload_with(
+        &self,
+        chain: &CertificateChain,
+        key: &PrivateKey,
+    ) -> Result<CertificateMetadata, TlsError> {
+        let state = self.state.lock();
+        let result = self.swap(chain, key);
+        drop(state);
+        self.finish(result)
+    }
+
+    /// Reloads from the PEM files.
+    pub(crate) fn reload_from_files(&self) -> Result<CertificateMetadata, TlsError> {
+        let (cert_path, key_path) = self.require_paths()?;
+        let mut state = self.state.lock();
+        state.stamps = Some([FileStamp::of(cert_path), FileStamp::of(key_path)]);
+        let result = self.load_and_swap(cert_path, key_path);
+        drop(state);
+        self.finish(result)
+    }
+
+    /// Reloads from the PEM files if either changed since the last attempt.
+    pub(crate) fn reload_if_changed(&self) -> Result<Option<CertificateMetadata>, TlsError> {
+        let (cert_path, key_path) = self.require_paths()?;
+        let mut state = self.state.lock();
+        let stamps = [FileStamp::of(cert_path), FileStamp::of(key_path)];
+        if state.stamps.as_ref() == Some(&stamps) {
+            return Ok(None);
+        }
+        // Record the attempt even if it fails, so a half-written rotation is
+        // retried when the other file lands rather than on every poll.
+        state.stamps = Some(stamps);
+        let result = self.load_and_swap(cert_path, key_path);
+        drop(state);
+        self.finish(result).map(Some)
+    }
+
+    /// Returns the validity left if the served certificate expires within
+    /// the warning window, emitting the warning the first time.
+    pub(crate) fn check_expiry(&self) -> Option<Duration> {
+        let current = self.metadata();
+        let now = self.options.clock.now();
+        if !current.expires_within(now, self.options.expiry_warning) {
+            return None;
+        }
+        let remaining = current.remaining_validity(now).unwrap_or_default();
+        let first = {
+            let mut state = self.state.lock();
+            let first = state.warned_serial.as_deref() != Some(current.serial.as_str());
+            if first {
+                state.warned_serial = Some(current.serial.clone());
+            }
+            first
+        };
+        if first {
+            emit(
+                self.options.listener.as_ref(),
+                &CertificateEvent::ExpiryWarning { current, remaining },
+            );
+        }
+        Some(remaining)
+    }
+
+    fn load_and_swap(
+        &self,
+        cert_path: &Path,
+        key_path: &Path,
+    ) -> Result<(CertificateMetadata, CertificateMetadata), TlsError> {
+        let chain = CertificateChain::from_pem_file(cert_path)?;
+        let key = PrivateKey::from_pem_file(key_path)?;
+        self.swap(&chain, &key)
+    }
+
+    fn swap(
+        &self,
+        chain: &CertificateChain,
+        key: &PrivateKey,
+    ) -> Result<(CertificateMetadata, CertificateMetadata), TlsError> {
+        if chain.is_empty() {
+            return Err(TlsError::Configuration("certificate chain is empty".into()));
+        }
+        if self.options.policy.require_full_chain && chain.len() < 2 {
+            return Err(TlsError::Configuration(format!(
+                "require_full_chain set but certificate chain has only {} cert(s)",
+                chain.len()
+            )));
+        }
+        if self.options.policy.strict {
+            TlsAcceptorBuilder::validate_certificate_chain_at(chain, self.options.clock.now())?;
+        }
+        let served = Arc::new(Served::new(chain, key, &self.provider)?);
+        let current = served.metadata.clone();
+        let previous = std::mem::replace(&mut *self.resolver.served.write(), served);
+        Ok((previous.metadata.clone(), current))
+    }
+
+    fn finish(
+        &self,
+        result: Result<(CertificateMetadata, CertificateMetadata), TlsError>,
+    ) -> Result<CertificateMetadata, TlsError> {
+        let listener = self.options.listener.as_ref();
+        match result {
+            Ok((previous, current)) => {
+                emit(
+                    listener,
+                    &CertificateEvent::Reloaded {
+                        previous,
+                        current: current.clone(),
+                    },
+                );
+                self.check_expiry();
+                Ok(current)
+            }
+            Err(err) => {
+                emit(
+                    listener,
+                    &CertificateEvent::ReloadRejected {
+                        reason: err.to_string(),
+                        current: self.metadata(),
+                    },
+                );
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Server-certificate verifier whose root store can be replaced at runtime.
+#[derive(Debug)]
+pub(crate) struct ReloadableServerVerifier {
+    current: RwLock<Arc<WebPkiServerVerifier>>,
+}
+
+impl ReloadableServerVerifier {
+    fn current(&self) -> Arc<WebPkiServerVerifier> {
+        Arc::clone(&self.current.read())
+    }
+}
+
+impl ServerCertVerifier for ReloadableServerVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.current().verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.current().verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.current().verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.current().supported_verify_schemes()
+    }
+}
+
+/// Rebuilds the connector's verifier from replacement root stores.
+#[derive(Debug)]
+pub(crate) struct RootReloader {
+    verifier: Arc<ReloadableServerVerifier>,
+    crls: Vec<CertificateRevocationListDer<'static>>,
+    provider: Arc<CryptoProvider>,
+    path: Option<PathBuf>,
+    listener: Option<CertificateEventListener>,
+    /// Serializes reloads; holds the file stamp last acted on.
+    stamp: Mutex<Option<FileStamp>>,
+}
+
+impl RootReloader {
+    pub(crate) fn new(
+        roots: RootCertStore,
+        crls: Vec<CertificateRevocationListDer<'static>>,
+        provider: Arc<CryptoProvider>,
+        path: Option<PathBuf>,
+        listener: Option<CertificateEventListener>,
+    ) -> Result<Self, TlsError> {
+        let verifier = Self::build_verifier(roots, &crls, &provider)?;
+        let stamp = path.as_deref().and_then(FileStamp::of);
+        Ok(Self {
+            verifier: Arc::new(ReloadableServerVerifier {
+                current: RwLock::new(verifier),
+            }),
+            crls,
+            provider,
+            path,
+            listener,
+            stamp: Mutex::new(stamp),
+        })
+    }
+
+    pub(crate) fn verifier(&self) -> Arc<ReloadableServerVerifier> {
+        Arc::clone(&self.verifier)
+    }
+
+    pub(crate) fn require_path(&self) -> Result<&Path, TlsError> {
+        self.path.as_deref().ok_or_else(|| {
+            TlsError::Configuration(
+                "root certificates were not loaded from a PEM file; \
+                 use reload_root_certificates_with"
+                    .into(),
+            )
+        })
+    }
+
+    pub(crate) fn reload_with(&self, roots: RootCertStore) -> Result<usize, TlsError> {
+        let guard = self.stamp.lock();
+        let result = self.swap(roots);
+        drop(guard);
+        self.finish(result)
+    }
+
+    /// Reloads from the PEM file.
+    pub(crate) fn reload_from_file(&self) -> Result<usize, TlsError> {
+        let path = self.require_path()?;
+        let mut last = self.stamp.lock();
+        *last = FileStamp::of(path);
+        let result = Self::load(path).and_then(|roots| self.swap(roots));
+        drop(last);
+        self.finish(result)
+    }
+
+    /// Reloads from the PEM file if it changed since the last attempt.
+    pub(crate) fn reload_if_changed(&self) -> Result<Option<usize>, TlsError> {
+        let path = self.require_path()?;
+        let mut last = self.stamp.lock();
+        let stamp = FileStamp::of(path);
+        if *last == stamp {
+            return Ok(None);
+        }
+        *last = stamp;
+        let result = Self::load(path).and_then(|roots| self.swap(roots));
+        drop(last);
+        self.finish(result).map(Some)
+    }
+
+    fn load(path: &Path) -> Result<RootCertStore, TlsError> {
+        let mut roots = RootCertStore::empty();
+        roots.add_pem_file(path)?;
+        Ok(roots)
+    }
+
+    fn swap(&self, roots: RootCertStore) -> Result<usize, TlsError> {
+        if roots.is_empty() {
+            return Err(TlsError::Certificate(
+                "replacement root store is empty — server certificates cannot be verified".into(),
+            ));
+        }
+        let count = roots.len();
+        let verifier = Self::build_verifier(roots, &self.crls, &self.provider)?;
+        *self.verifier.current.write() = verifier;
+        Ok(count)
+    }
+
+    fn finish(&self, result: Result<usize, TlsError>) -> Result<usize, TlsError> {
+        let event = match &result {
+            Ok(roots) => CertificateEvent::RootsReloaded { roots: *roots },
+            Err(err) => CertificateEvent::RootsReloadRejected {
+                reason: err.to_string(),
+            },
+        };
+        emit(self.listener.as_ref(), &event);
+        result
+    }
+
+    fn build_verifier(
+        roots: RootCertStore,
+        crls: &[CertificateRevocationListDer<'static>],
+        provider: &Arc<CryptoProvider>,
+    ) -> Result<Arc<WebPkiServerVerifier>, TlsError> {
+        WebPkiServerVerifier::builder_with_provider(
+            Arc::new(roots.into_inner()),
+            Arc::clone(provider),
+        )
+        .with_crls(crls.iter().cloned())
+        .build()
+        .map_err(|e| TlsError::Configuration(format!("root verifier build: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    const SERVER_CERT_PEM: &[u8] = include_bytes!("../../tests/fixtures/tls/server.crt");
+
+    fn fixture_metadata() -> CertificateMetadata {
+        let chain = CertificateChain::from_pem(SERVER_CERT_PEM).unwrap();
+        let leaf = chain.into_inner().remove(0);
+        CertificateMetadata::from_der(leaf.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn metadata_matches_fixture() {
+        let metadata = fixture_metadata();
+        assert_eq!(metadata.serial, "319CCC27734AFF08AF115D00C23643364B2C6C4B");
+        assert!(metadata.subject.contains("CN=localhost"), "{metadata:?}");
+        // notBefore=Apr 17 15:35:49 2026 GMT, notAfter=Apr 17 15:35:49 2027 GMT
+        assert_eq!(metadata.not_after, 1_807_976_149);
+        assert_eq!(metadata.not_before, 1_807_976_149 - 365 * 24 * 60 * 60);
+        assert_eq!(
+            metadata.not_after_time(),
+            UNIX_EPOCH + Duration::from_secs(1_807_976_149)
+        );
+    }
+
+    #[test]
+    fn remaining_validity_and_window() {
+        let metadata = fixture_metadata();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let not_after = Time::from_secs(1_807_976_149);
+
+        let ten_days_before = not_after.saturating_sub_nanos(10 * day.as_nanos() as u64);
+        assert_eq!(metadata.remaining_validity(ten_days_before), Some(10 * day));
+        assert!(metadata.expires_within(ten_days_before, 10 * day));
+        assert!(!metadata.expires_within(ten_days_before, 9 * day));
+
+        assert_eq!(metadata.remaining_validity(not_after), Some(Duration::ZERO));
+        let after = not_after.saturating_add_nanos(1);
+        assert_eq!(metadata.remaining_validity(after), None);
+        assert!(metadata.expires_within(after, Duration::ZERO));
+    }
+
+    #[test]
+    fn garbage_der_is_rejected() {
+        let err = CertificateMetadata::from_der(b"not a certificate").unwrap_err();
+        assert!(matches!(err, TlsError::Certificate(_)), "{err:?}");
+    }
+}
@@ -7,6 +7,10 @@
 use super::error::TlsError;
 #[cfg(feature = "tls")]
 use crate::io::{AsyncRead, AsyncWrite, ReadBuf};
+#[cfg(feature = "tls")]
+use crate::net::tcp::tap::{ConnectionTap, TapConfig, TapDirection};
+#[cfg(feature = "tls")]
+use crate::net::tcp::traits::TcpStreamApi;
 
 // When tracing integration is enabled, the `debug!/trace!/error!` macros come from `tracing`.
 // Import them explicitly so unqualified macro calls in this module compile under all feature sets.
@@ -21,6 +25,8 @@ use std::io;
 #[cfg(feature = "tls")]
 use std::pin::Pin;
 #[cfg(feature = "tls")]
+use std::sync::Arc;
+#[cfg(feature = "tls")]
 use std::task::{Context, Poll};
 
 /// Internal state of the TLS stream.
@@ -72,6 +78,8 @@ pub struct TlsStream<IO> {
     conn: TlsConnection,
     state: TlsState,
     read_closed: bool,
+    /// Plaintext byte tap attached with [`TlsStream::tap`].
+    tap: Option<Arc<ConnectionTap>>,
 }
 
 /// Fallback `TlsStream` when TLS is disabled.
@@ -196,6 +204,7 @@ impl<IO> TlsStream<IO> {
             conn: TlsConnection::Client(conn),
             state: TlsState::Handshaking,
             read_closed: false,
+            tap: None,
         }
     }
 
@@ -206,6 +215,7 @@ impl<IO> TlsStream<IO> {
             conn: TlsConnection::Server(conn),
             state: TlsState::Handshaking,
             read_closed: false,
+            tap: None,
         }
     }
 
@@ -266,6 +276,31 @@ impl<IO> TlsStream<IO> {
     }
 }
 
+#[cfg(feature = "tls")]
+impl<IO: TcpStreamApi> TlsStream<IO> {
+    /// Attaches a byte tap capturing the decrypted plaintext, replacing any
+    /// tap already attached.
+    ///
+    /// Chunks are recorded as the application reads and writes them, so the
+    /// capture shows the application protocol rather than TLS records.
+    /// See [`TcpStream::tap`](crate::net::TcpStream::tap).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer address cannot be determined.
+    pub fn tap(&mut self, config: TapConfig) -> io::Result<()> {
+        let peer = self.io.peer_addr()?;
+        let local = self.io.local_addr().ok();
+        self.tap = Some(ConnectionTap::new(peer, local, config));
+        Ok(())
+    }
+
+    /// Detaches the plaintext tap, if any.
+    pub fn untap(&mut self) {
+        self.tap = None;
+    }
+}
+
 #[cfg(not(feature = "tls"))]
 impl<IO> TlsStream<IO> {
     /// Get a reference to the underlying IO.
@@ -501,6 +536,10 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsStream<IO> {
                     if n > 0 {
                         #[cfg(feature = "tracing-integration")]
                         trace!(bytes = n, "TLS read");
+                        if let Some(tap) = &self.tap {
+                            let filled = buf.filled();
+                            tap.capture(TapDirection::Inbound, &filled[filled.len() - n..]);
+                        }
                         return Poll::Ready(Ok(()));
                     }
                     // Reader EOF: no more plaintext can arrive.
@@ -567,6 +606,9 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsStream<IO> {
         let n = io::Write::write(&mut self.conn.writer(), buf)?;
         #[cfg(feature = "tracing-integration")]
         trace!(bytes = n, "TLS write");
+        if let Some(tap) = &self.tap {
+            tap.capture(TapDirection::Outbound, &buf[..n]);
+        }
 
         // When rustls returns Ok(0) with a non-empty buffer, the internal
         // plaintext buffer is full. Flush pending TLS records to make room,
@@ -591,6 +633,9 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsStream<IO> {
                 cx.waker().wake_by_ref();
                 return Poll::Pending;
             }
+            if let Some(tap) = &self.tap {
+                tap.capture(TapDirection::Outbound, &buf[..retry]);
+            }
             return Poll::Ready(Ok(retry));
         }
 
@@ -888,4 +933,74 @@ mod tests {
         assert_eq!(checkpoints.len(), 2);
         assert!(runtime.is_quiescent());
     }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn tls_stream_tap_captures_plaintext() {
+        use crate::io::{AsyncReadExt, AsyncWriteExt};
+        use crate::net::tcp::tap::{RingSink, TapBudget, TapConfig, TapDirection};
+
+        init_test_logging();
+        let config = TestConfig::new()
+            .with_seed(0x7A97_1150)
+            .with_tracing(true)
+            .with_max_steps(20_000);
+        let mut runtime = LabRuntimeTarget::create_runtime(config);
+        let ring = Arc::new(RingSink::new(64));
+        let sink = Arc::clone(&ring);
+
+        let (connection_id, peer) = LabRuntimeTarget::block_on(&mut runtime, async move {
+            let chain = CertificateChain::from_pem(TEST_CERT_PEM).unwrap();
+            let key = PrivateKey::from_pem(TEST_KEY_PEM).unwrap();
+            let acceptor = TlsAcceptorBuilder::new(chain, key).build().unwrap();
+            let certs = Certificate::from_pem(TEST_CERT_PEM).unwrap();
+            let connector = TlsConnectorBuilder::new()
+                .add_root_certificates(certs)
+                .build()
+                .unwrap();
+
+            let server_name = ServerName::try_from("localhost".to_string()).unwrap();
+            let client_conn =
+                ClientConnection::new(Arc::clone(connector.config()), server_name).unwrap();
+            let server_conn = ServerConnection::new(Arc::clone(acceptor.config())).unwrap();
+            let client_addr = "127.0.0.1:5210".parse().unwrap();
+            let (client_io, server_io) =
+                VirtualTcpStream::pair(client_addr, "127.0.0.1:5211".parse().unwrap());
+
+            let mut client = TlsStream::new_client(client_io, client_conn);
+            let mut server = TlsStream::new_server(server_io, server_conn);
+            server
+                .tap(
+                    TapConfig::new(TapDirection::Both, 1024, sink)
+                        .with_budget(Arc::new(TapBudget::new(usize::MAX))),
+                )
+                .expect("attach tap");
+            let connection_id = server.tap.as_ref().map(|tap| tap.connection_id());
+
+            let (client_result, server_result) = zip(
+                poll_fn(|cx| client.poll_handshake(cx)),
+                poll_fn(|cx| server.poll_handshake(cx)),
+            )
+            .await;
+            client_result.expect("client handshake should succeed");
+            server_result.expect("server handshake should succeed");
+
+            client.write_all(b"secret request").await.unwrap();
+            client.flush().await.unwrap();
+            let mut request = [0_u8; 14];
+            server.read_exact(&mut request).await.unwrap();
+            server.write_all(b"secret response").await.unwrap();
+            server.flush().await.unwrap();
+            let mut response = [0_u8; 15];
+            client.read_exact(&mut response).await.unwrap();
+            assert_eq!(&request, b"secret request");
+            assert_eq!(&response, b"secret response");
+            (connection_id.expect("tapped"), client_addr)
+        });
+
+        assert_eq!(ring.bytes(connection_id, TapDirection::Inbound), b"secret request");
+        assert_eq!(ring.bytes(connection_id, TapDirection::Outbound), b"secret response");
+        assert!(ring.records().iter().all(|record| record.peer == peer));
+        assert!(runtime.is_quiescent());
+    }
 }
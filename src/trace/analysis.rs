@@ -0,0 +1,812 @@
+//! Post-run analysis of recorded traces: critical path, folded stacks and
+//! semantic diffing.
+//!
+//! A lab report says how many steps a scenario took, not where its virtual
+//! time went. This module rebuilds the task dependency graph from a recorded
+//! [`TraceEvent`] stream and answers that question two ways:
+//!
+//! - [`critical_path`] finds the longest chain of causally dependent spans
+//!   and attributes its virtual time to the tasks along it.
+//! - [`folded_stacks`] renders per-task virtual time as folded stacks
+//!   (`frame;frame;frame value`), the input format of `inferno` and
+//!   `flamegraph.pl`. The frames are the region hierarchy down to the task.
+//! - [`semantic_diff`] compares two replay traces by structural position
+//!   rather than raw ids; see [`diff`].
+//!
+//! The `_annotated` variants take [`TraceAnnotations`] from
+//! [`annotate`](crate::trace::annotate) and name tasks by their spawn site
+//! instead of their id wherever the annotations know it.
+//!
+//! # Model
+//!
+//! A *span* is one activation of a task: it opens when the task becomes
+//! runnable (spawn, wake, schedule or poll) and closes when the task yields
+//! or completes. Spans depend on each other through:
+//!
+//! - **continuation**: a task's previous span precedes its next one;
+//! - **spawn** and **wake**: the task that ran last caused the event, so its
+//!   span precedes the new span of the spawned or woken task. Joins and
+//!   channel hand-offs appear here, since both are delivered as wakes;
+//! - **down** and **exit**: a monitored or linked task's last span precedes
+//!   the watcher's next span.
+//!
+//! The critical path is walked backwards from the span that ends last,
+//! always following the dependency that was satisfied last. Each span on the
+//! path is charged the virtual time between that dependency and the point
+//! where the path leaves it, so the charges add up to the path length.
+//!
+//! # Partial traces
+//!
+//! Any prefix, suffix or window of a trace can be analyzed, including the
+//! events salvaged from a damaged recording. A span still open at the end is
+//! closed at the last event's time, and a yield or completion whose opening
+//! event was cut off starts at the first event's time; both are marked
+//! `truncated`. Only event order and timestamps are used, never sequence
+//! numbers, so a slice covering the same activity gives the same answer as
+//! the full trace. Iteration is over ordered maps throughout, which keeps
+//! the output deterministic.
+
+pub mod diff;
+
+pub use diff::{
+    AlignedEvent, DiffOptions, Divergence, DivergenceKind, DivergenceSeverity,
+    TRACE_DIFF_SCHEMA_VERSION, TraceDiffReport, semantic_diff,
+};
+
+use crate::trace::{TraceAnnotations, TraceData, TraceEvent, TraceEventKind};
+use crate::types::{RegionId, TaskId, Time};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Why one span depends on another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    /// The same task's previous span.
+    Continuation,
+    /// The spawning task.
+    Spawn,
+    /// The task that woke this one (joins, channels, notifications).
+    Wake,
+    /// A monitored task whose termination was delivered as a Down.
+    Down,
+    /// A linked task whose exit signal was delivered.
+    Exit,
+}
+
+impl DependencyKind {
+    /// Returns the stable lowercase name used in reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Continuation => "continuation",
+            Self::Spawn => "spawn",
+            Self::Wake => "wake",
+            Self::Down => "down",
+            Self::Exit => "exit",
+        }
+    }
+}
+
+impl fmt::Display for DependencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One activation of a task, from becoming runnable to yielding or completing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// The task that ran.
+    pub task: TaskId,
+    /// The task's region, if the trace named it.
+    pub region: Option<RegionId>,
+    /// Virtual time the span opened.
+    pub start: Time,
+    /// Virtual time the span closed.
+    pub end: Time,
+    /// True if an edge of the trace cut the span short.
+    pub truncated: bool,
+}
+
+impl Span {
+    /// Virtual time covered by the span, in nanoseconds.
+    #[must_use]
+    pub const fn duration_nanos(&self) -> u64 {
+        self.end.as_nanos().saturating_sub(self.start.as_nanos())
+    }
+}
+
+/// A span on the critical path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CriticalPathStep {
+    /// The span.
+    pub span: Span,
+    /// Attribution label of the span's task (see [`TaskAttribution::label`]).
+    pub label: String,
+    /// How this step depends on the previous one; `None` for the first step.
+    pub via: Option<DependencyKind>,
+    /// Virtual time charged to this step, in nanoseconds.
+    pub charged_nanos: u64,
+}
+
+/// Virtual time attributed to one task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskAttribution {
+    /// The task.
+    pub task: TaskId,
+    /// The region the task was spawned into, if known.
+    pub region: Option<RegionId>,
+    /// The task that was running when this one was spawned, if known.
+    pub spawned_by: Option<TaskId>,
+    /// `region/task` label, e.g. `R1/T3`, or just the task when the region
+    /// is unknown.
+    pub label: String,
+    /// Number of spans the task ran.
+    pub spans: usize,
+    /// Virtual time across all of the task's spans, in nanoseconds.
+    pub total_nanos: u64,
+    /// Virtual time the task contributed to the critical path, in nanoseconds.
+    pub critical_nanos: u64,
+}
+
+/// Result of [`critical_path`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CriticalPathReport {
+    /// Number of spans reconstructed from the trace.
+    pub spans: usize,
+    /// Number of those spans cut short by the edges of the trace.
+    pub truncated_spans: usize,
+    /// Length of the critical path in virtual nanoseconds.
+    pub length_nanos: u64,
+    /// The critical path, first span first.
+    pub path: Vec<CriticalPathStep>,
+    /// Every task, by critical-path time, then total time, then id.
+    pub tasks: Vec<TaskAttribution>,
+}
+
+impl CriticalPathReport {
+    /// Returns the task contributing the most time to the critical path.
+    #[must_use]
+    pub fn dominant(&self) -> Option<&TaskAttribution> {
+        self.tasks.first().filter(|task| task.critical_nanos > 0)
+    }
+}
+
+impl fmt::Display for CriticalPathReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Critical path: {:?} across {} span(s) ({} reconstructed, {} truncated)",
+            Duration::from_nanos(self.length_nanos),
+            self.path.len(),
+            self.spans,
+            self.truncated_spans
+        )?;
+        for (index, step) in self.path.iter().enumerate() {
+            let via = step
+                .via
+                .map_or_else(String::new, |kind| format!(" via {kind}"));
+            let truncated = if step.span.truncated {
+                " (truncated)"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "  #{index} {}{via} +{:?}{truncated}",
+                step.label,
+                Duration::from_nanos(step.charged_nanos)
+            )?;
+        }
+        writeln!(f, "Tasks by critical-path time:")?;
+        for task in &self.tasks {
+            writeln!(
+                f,
+                "  {} critical {:?}, total {:?}, {} span(s)",
+                task.label,
+                Duration::from_nanos(task.critical_nanos),
+                Duration::from_nanos(task.total_nanos),
+                task.spans
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the critical path through the task dependencies in `trace`.
+///
+/// See the [module documentation](self) for the span model and how partial
+/// traces are handled.
+#[must_use]
+pub fn critical_path(trace: &[TraceEvent]) -> CriticalPathReport {
+    Graph::build(trace).report()
+}
+
+/// [`critical_path`] with tasks labelled `region/site` from `annotations`.
+///
+/// Tasks the annotations do not cover keep their `region/task` label.
+#[must_use]
+pub fn critical_path_annotated(
+    trace: &[TraceEvent],
+    annotations: &TraceAnnotations,
+) -> CriticalPathReport {
+    Graph::build(trace).with_names(annotations).report()
+}
+
+/// Renders the virtual time of every task in `trace` as folded stacks.
+///
+/// One line per task, `R0;R1;T3 <nanos>`, sorted; tasks that consumed no
+/// virtual time are left out. Feed the output to `inferno-flamegraph` or
+/// `flamegraph.pl`.
+#[must_use]
+pub fn folded_stacks(trace: &[TraceEvent]) -> String {
+    Graph::build(trace).folded()
+}
+
+/// [`folded_stacks`] with task frames named by spawn site.
+///
+/// Tasks spawned from the same site under the same regions share a frame,
+/// so their time is summed into one line.
+#[must_use]
+pub fn folded_stacks_annotated(trace: &[TraceEvent], annotations: &TraceAnnotations) -> String {
+    Graph::build(trace).with_names(annotations).folded()
+}
+
+/// A dependency of a span on an earlier one.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: usize,
+    kind: DependencyKind,
+    /// When the dependency was satisfied.
+    at: Time,
+    /// Trace position of the satisfying event.
+    order: usize,
+}
+
+#[derive(Debug)]
+struct SpanNode {
+    span: Span,
+    /// Edges always point at lower indices, so the graph is acyclic.
+    preds: Vec<Edge>,
+    /// Trace position of the event that closed the span.
+    end_order: usize,
+}
+
+#[derive(Debug, Default)]
+struct TaskInfo {
+    region: Option<RegionId>,
+    spawned_by: Option<TaskId>,
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    spans: Vec<SpanNode>,
+    open: BTreeMap<TaskId, usize>,
+    last: BTreeMap<TaskId, usize>,
+    /// Dependencies waiting for the task's next span.
+    pending: BTreeMap<TaskId, Vec<Edge>>,
+    tasks: BTreeMap<TaskId, TaskInfo>,
+    regions: BTreeMap<RegionId, Option<RegionId>>,
+    /// Resolved spawn-site names, used in place of task ids.
+    names: BTreeMap<TaskId, String>,
+    /// The task that ran last.
+    current: Option<TaskId>,
+    first_time: Option<Time>,
+    last_time: Time,
+}
+
+impl Graph {
+    fn build(trace: &[TraceEvent]) -> Self {
+        let mut graph = Self::default();
+        for (order, event) in trace.iter().enumerate() {
+            graph.first_time.get_or_insert(event.time);
+            graph.last_time = graph.last_time.max(event.time);
+            graph.apply(order, event);
+        }
+        let (end, order) = (graph.last_time, trace.len());
+        for (task, index) in std::mem::take(&mut graph.open) {
+            let node = &mut graph.spans[index];
+            node.span.end = end.max(node.span.start);
+            node.span.truncated = true;
+            node.end_order = order;
+            graph.last.insert(task, index);
+        }
+        graph
+    }
+
+    fn with_names(mut self, annotations: &TraceAnnotations) -> Self {
+        self.names = annotations.names();
+        self
+    }
+
+    fn apply(&mut self, order: usize, event: &TraceEvent) {
+        let at = event.time;
+        match event.data {
+            TraceData::Task { task, region } => {
+                let info = self.tasks.entry(task).or_default();
+                info.region.get_or_insert(region);
+                match event.kind {
+                    TraceEventKind::Spawn | TraceEventKind::TaskAdmitted => {
+                        if let Some(parent) = self.current.filter(|&parent| parent != task) {
+                            let info = self.tasks.entry(task).or_default();
+                            info.spawned_by.get_or_insert(parent);
+                        }
+                        self.activate(task, Some(DependencyKind::Spawn), at, order);
+                    }
+                    TraceEventKind::Wake => {
+                        self.activate(task, Some(DependencyKind::Wake), at, order);
+                    }
+                    TraceEventKind::Schedule | TraceEventKind::Poll => {
+                        self.activate(task, None, at, order);
+                        self.current = Some(task);
+                    }
+                    TraceEventKind::Yield | TraceEventKind::Complete => {
+                        self.close(task, at, order);
+                        self.current = Some(task);
+                    }
+                    _ => {}
+                }
+            }
+            TraceData::Region { region, parent } if event.kind == TraceEventKind::RegionCreated => {
+                self.regions.entry(region).or_insert(parent);
+            }
+            TraceData::Down {
+                watcher, monitored, ..
+            } => {
+                self.depend(monitored, watcher, DependencyKind::Down, at, order);
+            }
+            TraceData::Exit { from, to, .. } => {
+                self.depend(from, to, DependencyKind::Exit, at, order);
+            }
+            _ => {}
+        }
+    }
+
+    /// The span `task` is running, or else the one it ran last.
+    fn latest_span(&self, task: TaskId) -> Option<usize> {
+        self.open.get(&task).or_else(|| self.last.get(&task)).copied()
+    }
+
+    /// Records that `to`'s next span depends on `from`'s latest one.
+    fn depend(&mut self, from: TaskId, to: TaskId, kind: DependencyKind, at: Time, order: usize) {
+        if let Some(from) = self.latest_span(from) {
+            self.pending.entry(to).or_default().push(Edge {
+                from,
+                kind,
+                at,
+                order,
+            });
+        }
+    }
+
+    /// Opens a span for `task` unless one is open, returning its index.
+    /// `cause` links the new span to the span of the task that ran last.
+    fn activate(
+        &mut self,
+        task: TaskId,
+        cause: Option<DependencyKind>,
+        at: Time,
+        order: usize,
+    ) -> usize {
+        if let Some(&index) = self.open.get(&task) {
+            return index;
+        }
+        let mut preds = self.pending.remove(&task).unwrap_or_default();
+        if let Some(&prev) = self.last.get(&task) {
+            let prev_node = &self.spans[prev];
+            preds.push(Edge {
+                from: prev,
+                kind: DependencyKind::Continuation,
+                at: prev_node.span.end,
+                order: prev_node.end_order,
+            });
+        }
+        if let Some(kind) = cause
+            && let Some(from) = self
+                .current
+                .filter(|&current| current != task)
+                .and_then(|current| self.latest_span(current))
+        {
+            preds.push(Edge {
+                from,
+                kind,
+                at,
+                order,
+            });
+        }
+        let region = self.tasks.get(&task).and_then(|info| info.region);
+        let index = self.spans.len();
+        self.spans.push(SpanNode {
+            span: Span {
+                task,
+                region,
+                start: at,
+                end: at,
+                truncated: false,
+            },
+            preds,
+            end_order: order,
+        });
+        self.open.insert(task, index);
+        index
+    }
+
+    fn close(&mut self, task: TaskId, at: Time, order: usize) {
+        let index = self.open.remove(&task).unwrap_or_else(|| {
+            // The event that opened this span is not in the trace: resume
+            // from the task's previous span, or from the start of the trace.
+            let previous = self.last.get(&task).map(|&prev| self.spans[prev].span.end);
+            let start = previous.or(self.first_time).unwrap_or(at);
+            let index = self.activate(task, None, start, order);
+            self.open.remove(&task);
+            self.spans[index].span.truncated = previous.is_none();
+            index
+        });
+        let node = &mut self.spans[index];
+        node.span.end = at.max(node.span.start);
+        node.end_order = order;
+        self.last.insert(task, index);
+    }
+
+    /// The task's resolved name, or else its id.
+    fn task_name(&self, task: TaskId) -> String {
+        self.names
+            .get(&task)
+            .cloned()
+            .unwrap_or_else(|| task.to_string())
+    }
+
+    fn label(&self, task: TaskId) -> String {
+        let name = self.task_name(task);
+        self.tasks
+            .get(&task)
+            .and_then(|info| info.region)
+            .map_or_else(|| name.clone(), |region| format!("{region}/{name}"))
+    }
+
+    /// Region ancestry of `task`, root first, followed by the task itself.
+    fn stack(&self, task: TaskId) -> Vec<String> {
+        let mut frames = vec![self.task_name(task)];
+        let mut region = self.tasks.get(&task).and_then(|info| info.region);
+        // Bounded by the number of known regions in case of a malformed cycle.
+        for _ in 0..=self.regions.len() {
+            let Some(current) = region else { break };
+            frames.push(current.to_string());
+            region = self.regions.get(&current).copied().flatten();
+        }
+        frames.reverse();
+        frames
+    }
+
+    /// Folded stacks of per-task virtual time, identical stacks summed.
+    fn folded(&self) -> String {
+        let mut stacks: BTreeMap<String, u64> = BTreeMap::new();
+        for (task, total) in self.task_totals() {
+            if total > 0 {
+                *stacks.entry(self.stack(task).join(";")).or_insert(0) += total;
+            }
+        }
+        stacks
+            .into_iter()
+            .map(|(stack, total)| format!("{stack} {total}\n"))
+            .collect()
+    }
+
+    fn task_totals(&self) -> BTreeMap<TaskId, u64> {
+        let mut totals = BTreeMap::new();
+        for node in &self.spans {
+            *totals.entry(node.span.task).or_insert(0u64) += node.span.duration_nanos();
+        }
+        totals
+    }
+
+    /// Walks back from the span that ends last, following the dependency
+    /// satisfied last, and returns `(span, via, charged)` first step first.
+    fn walk_critical_path(&self) -> Vec<(usize, Option<DependencyKind>, u64)> {
+        let Some(mut index) = (0..self.spans.len())
+            .max_by_key(|&index| (self.spans[index].span.end, self.spans[index].end_order))
+        else {
+            return Vec::new();
+        };
+        let mut cutoff = self.spans[index].span.end;
+        let mut steps = Vec::new();
+        loop {
+            let node = &self.spans[index];
+            let Some(edge) = node.preds.iter().max_by_key(|edge| (edge.order, edge.kind)) else {
+                let charged = cutoff.as_nanos().saturating_sub(node.span.start.as_nanos());
+                steps.push((index, None, charged));
+                break;
+            };
+            let from = &self.spans[edge.from].span;
+            let upper = from.end.min(cutoff);
+            let boundary = edge.at.min(upper).max(from.start.min(upper));
+            let charged = cutoff.as_nanos().saturating_sub(boundary.as_nanos());
+            steps.push((index, Some(edge.kind), charged));
+            index = edge.from;
+            cutoff = boundary;
+        }
+        steps.reverse();
+        steps
+    }
+
+    fn report(&self) -> CriticalPathReport {
+        let steps = self.walk_critical_path();
+        let mut critical: BTreeMap<TaskId, u64> = BTreeMap::new();
+        let path: Vec<CriticalPathStep> = steps
+            .into_iter()
+            .map(|(index, via, charged_nanos)| {
+                let span = self.spans[index].span.clone();
+                *critical.entry(span.task).or_insert(0) += charged_nanos;
+                CriticalPathStep {
+                    label: self.label(span.task),
+                    span,
+                    via,
+                    charged_nanos,
+                }
+            })
+            .collect();
+
+        let mut span_counts: BTreeMap<TaskId, usize> = BTreeMap::new();
+        for node in &self.spans {
+            *span_counts.entry(node.span.task).or_insert(0) += 1;
+        }
+        let mut tasks: Vec<TaskAttribution> = self
+            .task_totals()
+            .into_iter()
+            .map(|(task, total_nanos)| {
+                let info = self.tasks.get(&task);
+                TaskAttribution {
+                    task,
+                    region: info.and_then(|info| info.region),
+                    spawned_by: info.and_then(|info| info.spawned_by),
+                    label: self.label(task),
+                    spans: span_counts.get(&task).copied().unwrap_or(0),
+                    total_nanos,
+                    critical_nanos: critical.get(&task).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+        tasks.sort_by(|a, b| {
+            b.critical_nanos
+                .cmp(&a.critical_nanos)
+                .then(b.total_nanos.cmp(&a.total_nanos))
+                .then(a.task.cmp(&b.task))
+        });
+
+        CriticalPathReport {
+            spans: self.spans.len(),
+            truncated_spans: self.spans.iter().filter(|node| node.span.truncated).count(),
+            length_nanos: path.iter().map(|step| step.charged_nanos).sum(),
+            path,
+            tasks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn task(n: u32) -> TaskId {
+        TaskId::new_for_test(n, 0)
+    }
+
+    fn region(n: u32) -> RegionId {
+        RegionId::new_for_test(n, 0)
+    }
+
+    fn ms(n: u64) -> Time {
+        Time::from_millis(n)
+    }
+
+    /// T1 spawns a quick task T2 and a slow task T3 (in child region R1),
+    /// then waits for both. T3 is the bottleneck.
+    fn bottleneck_trace() -> Vec<TraceEvent> {
+        let (r0, r1) = (region(0), region(1));
+        let (t1, t2, t3) = (task(1), task(2), task(3));
+        vec![
+            TraceEvent::region_created(0, ms(0), r0, None),
+            TraceEvent::spawn(1, ms(0), t1, r0),
+            TraceEvent::poll(2, ms(0), t1, r0),
+            TraceEvent::region_created(3, ms(0), r1, Some(r0)),
+            TraceEvent::spawn(4, ms(0), t2, r0),
+            TraceEvent::spawn(5, ms(0), t3, r1),
+            TraceEvent::yield_task(6, ms(1), t1, r0),
+            TraceEvent::poll(7, ms(1), t2, r0),
+            TraceEvent::complete(8, ms(3), t2, r0),
+            TraceEvent::wake(9, ms(3), t1, r0),
+            TraceEvent::poll(10, ms(3), t1, r0),
+            TraceEvent::yield_task(11, ms(3), t1, r0),
+            TraceEvent::poll(12, ms(3), t3, r1),
+            TraceEvent::complete(13, ms(10), t3, r1),
+            TraceEvent::wake(14, ms(10), t1, r0),
+            TraceEvent::poll(15, ms(10), t1, r0),
+            TraceEvent::complete(16, ms(12), t1, r0),
+        ]
+    }
+
+    fn path_summary(report: &CriticalPathReport) -> Vec<(String, Option<DependencyKind>, u64)> {
+        report
+            .path
+            .iter()
+            .map(|step| (step.label.clone(), step.via, step.charged_nanos))
+            .collect()
+    }
+
+    #[test]
+    fn bottleneck_task_dominates_critical_path() {
+        init_test("bottleneck_task_dominates_critical_path");
+        let report = critical_path(&bottleneck_trace());
+
+        let expected = vec![
+            ("R0/T1".to_string(), None, 0),
+            ("R1/T3".to_string(), Some(DependencyKind::Spawn), 10_000_000),
+            ("R0/T1".to_string(), Some(DependencyKind::Wake), 2_000_000),
+        ];
+        let actual = path_summary(&report);
+        crate::assert_with_log!(actual == expected, "critical path", expected, actual);
+        crate::assert_with_log!(
+            report.length_nanos == 12_000_000,
+            "path covers the whole run",
+            12_000_000,
+            report.length_nanos
+        );
+
+        let dominant = report.dominant().expect("dominant task");
+        crate::assert_with_log!(dominant.task == task(3), "bottleneck", task(3), dominant.task);
+        crate::assert_with_log!(
+            dominant.spawned_by == Some(task(1)),
+            "spawn site",
+            Some(task(1)),
+            dominant.spawned_by
+        );
+        let order: Vec<_> = report.tasks.iter().map(|t| t.task).collect();
+        let expected_order = vec![task(3), task(1), task(2)];
+        crate::assert_with_log!(order == expected_order, "ranking", expected_order, order);
+        crate::assert_with_log!(report.spans == 5, "spans", 5, report.spans);
+        crate::assert_with_log!(report.truncated_spans == 0, "complete", 0, report.truncated_spans);
+
+        let again = critical_path(&bottleneck_trace());
+        crate::assert_with_log!(again == report, "deterministic", true, again == report);
+        crate::test_complete!("bottleneck_task_dominates_critical_path");
+    }
+
+    #[test]
+    fn folded_stacks_follow_region_hierarchy() {
+        init_test("folded_stacks_follow_region_hierarchy");
+        let folded = folded_stacks(&bottleneck_trace());
+        insta::assert_snapshot!("critical_path_folded_stacks", folded);
+        crate::test_complete!("folded_stacks_follow_region_hierarchy");
+    }
+
+    #[test]
+    fn slice_covering_the_same_window_agrees() {
+        init_test("slice_covering_the_same_window_agrees");
+        let scenario = bottleneck_trace();
+        let mut original = vec![
+            TraceEvent::rng_seed(0, ms(0), 42),
+            TraceEvent::user_trace(1, ms(0), "warmup"),
+        ];
+        original.extend(scenario.iter().cloned().map(|mut event| {
+            event.seq += 2;
+            event
+        }));
+        original.push(TraceEvent::checkpoint(19, ms(20), 1, 0, 1));
+        original.push(TraceEvent::user_trace(20, ms(20), "teardown"));
+        let window = &original[2..2 + scenario.len()];
+
+        let full = critical_path(&original);
+        let sliced = critical_path(window);
+        crate::assert_with_log!(full == sliced, "critical path agrees", full, sliced);
+        let full_folded = folded_stacks(&original);
+        let sliced_folded = folded_stacks(window);
+        crate::assert_with_log!(
+            full_folded == sliced_folded,
+            "folded stacks agree",
+            full_folded,
+            sliced_folded
+        );
+        crate::test_complete!("slice_covering_the_same_window_agrees");
+    }
+
+    #[test]
+    fn truncated_traces_are_analyzed_gracefully() {
+        init_test("truncated_traces_are_analyzed_gracefully");
+        let trace = bottleneck_trace();
+
+        // Cut before T3 completes: its span is closed at the last event.
+        let tail_cut = critical_path(&trace[..13]);
+        crate::assert_with_log!(
+            tail_cut.truncated_spans == 1,
+            "open span truncated",
+            1,
+            tail_cut.truncated_spans
+        );
+        let last = tail_cut.path.last().expect("path");
+        crate::assert_with_log!(last.span.task == task(3), "ends at T3", task(3), last.span.task);
+        crate::assert_with_log!(last.span.truncated, "marked truncated", true, last.span.truncated);
+        crate::assert_with_log!(
+            tail_cut.length_nanos == 3_000_000,
+            "length up to the cut",
+            3_000_000,
+            tail_cut.length_nanos
+        );
+
+        // Cut just before T2 completes: its opening events are gone.
+        let head_cut = critical_path(&trace[8..]);
+        crate::assert_with_log!(
+            head_cut.truncated_spans == 1,
+            "orphan completion truncated",
+            1,
+            head_cut.truncated_spans
+        );
+        let dominant = head_cut.dominant().map(|t| t.task);
+        crate::assert_with_log!(dominant == Some(task(3)), "still T3", Some(task(3)), dominant);
+        crate::assert_with_log!(
+            head_cut.length_nanos == 9_000_000,
+            "length from T3's first poll",
+            9_000_000,
+            head_cut.length_nanos
+        );
+
+        let empty = critical_path(&[]);
+        crate::assert_with_log!(empty.path.is_empty(), "empty trace", true, empty.path.is_empty());
+        let folded = folded_stacks(&[]);
+        crate::assert_with_log!(folded.is_empty(), "no stacks", "", folded);
+        crate::test_complete!("truncated_traces_are_analyzed_gracefully");
+    }
+
+    #[test]
+    fn annotated_analysis_prefers_site_names() {
+        init_test("annotated_analysis_prefers_site_names");
+        let worker = crate::trace::SymbolEntry::new(
+            "app",
+            "app::worker",
+            "src/worker.rs",
+            7,
+            crate::trace::SiteKind::Spawn,
+        );
+        let mut map = crate::trace::SymbolMap::new();
+        map.insert(worker.clone());
+        let unknown = crate::trace::SpawnSiteId::of("src/gone.rs", 99);
+        let sites =
+            BTreeMap::from([(task(1), worker.id), (task(2), worker.id), (task(3), unknown)]);
+        let trace = bottleneck_trace();
+        let annotations = crate::trace::annotate(&trace, &sites, &map);
+
+        let folded = folded_stacks_annotated(&trace, &annotations);
+        let expected =
+            format!("R0;R1;{unknown} 10000000\nR0;app::worker@src/worker.rs:7 6000000\n");
+        crate::assert_with_log!(folded == expected, "site frames merge", expected, folded);
+
+        let report = critical_path_annotated(&trace, &annotations);
+        let labels: Vec<&str> = report.tasks.iter().map(|task| task.label.as_str()).collect();
+        let expected = vec![
+            format!("R1/{unknown}"),
+            "R0/app::worker@src/worker.rs:7".to_string(),
+            "R0/app::worker@src/worker.rs:7".to_string(),
+        ];
+        crate::assert_with_log!(labels == expected, "labels use sites", expected, labels);
+
+        let plain = critical_path(&trace);
+        let label = &plain.tasks[0].label;
+        crate::assert_with_log!(label == "R1/T3", "plain keeps ids", "R1/T3", label);
+        crate::test_complete!("annotated_analysis_prefers_site_names");
+    }
+}
@@ -0,0 +1,1196 @@
+//! Semantic diff of two replay traces.
+//!
+//! Byte-diffing two recordings of the same scenario tells you nothing: task,
+//! region and timer ids and I/O tokens are allocated afresh on every run, and
+//! virtual timestamps shift with the smallest change. [`semantic_diff`]
+//! instead names every entity by its structural position and compares the two
+//! event streams by those names.
+//!
+//! # Structural names
+//!
+//! - A region is named by its parent and its ordinal among the parent's child
+//!   regions: `r0`, `r0.1`, `r0.1.0`.
+//! - A task is named by its region and its ordinal among the tasks spawned
+//!   into that region: `r0.1/t2`. A spawn-site label, when the caller
+//!   supplies one, travels with the spawn but is not part of the name, so a
+//!   renamed spawn site is reported once as a [`DivergenceKind::Label`]
+//!   divergence and the task's later events still line up.
+//! - Timers and I/O tokens, the channel-like resources a replay trace
+//!   records, are named by their creation site: the task that was running
+//!   when they were created or first used, plus an ordinal, as in
+//!   `r0/t1#timer0` or `r0/t1#io0`.
+//! - An entity whose creation falls outside the trace, as in a window cut
+//!   from a longer recording, is named by order of first appearance (`?r0`,
+//!   `?t0`, `?timer0`), so partial traces still compare.
+//!
+//! Ticks and virtual times are ignored. The rest of the payload (outcomes,
+//! readiness, byte counts, RNG values, chaos kinds) is compared verbatim.
+//!
+//! # Alignment
+//!
+//! The streams are walked in lockstep through a lookahead window of
+//! [`DiffOptions::window`] events per trace, so the work per event is bounded
+//! by the window, not the trace length, and neither trace is held in memory.
+//! When the heads differ, each is searched for in the other trace's window:
+//!
+//! - found on both sides: the nearer one was moved, a
+//!   [`DivergenceKind::Reorder`];
+//! - found on one side only: the other head has no counterpart,
+//!   [`DivergenceKind::OnlyInA`] or [`DivergenceKind::OnlyInB`];
+//! - found on neither: the heads are paired and reported as a
+//!   [`DivergenceKind::Kind`] or [`DivergenceKind::Payload`] mismatch.
+//!
+//! When one trace ends first, the rest of the other is summarized as a single
+//! [`DivergenceKind::Truncated`] divergence.
+//!
+//! A reorder is [`DivergenceSeverity::Benign`] when the moved event is
+//! causally independent of every event it moved across: it concerns a
+//! different entity, not an ancestor or descendant of theirs, and none of the
+//! events is global (time, RNG, checkpoints, batched wakes). Label changes are
+//! benign too, since they alter no behavior. Everything else is
+//! [`DivergenceSeverity::Significant`].
+
+use crate::trace::TraceAnnotations;
+use crate::trace::replay::{CompactRegionId, CompactTaskId, ReplayEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+/// Version of the [`TraceDiffReport`] JSON schema.
+pub const TRACE_DIFF_SCHEMA_VERSION: u32 = 1;
+
+/// What a [`Divergence`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// The traces have different event kinds at the same position.
+    Kind,
+    /// Same event kind, different subject or payload.
+    Payload,
+    /// The same spawn carries different spawn-site labels.
+    Label,
+    /// An event appears in both traces at different positions.
+    Reorder,
+    /// An event of trace A has no counterpart in trace B.
+    OnlyInA,
+    /// An event of trace B has no counterpart in trace A.
+    OnlyInB,
+    /// One trace ended while the other went on.
+    Truncated,
+}
+
+impl DivergenceKind {
+    /// Returns the stable snake-case name used in reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Kind => "kind",
+            Self::Payload => "payload",
+            Self::Label => "label",
+            Self::Reorder => "reorder",
+            Self::OnlyInA => "only_in_a",
+            Self::OnlyInB => "only_in_b",
+            Self::Truncated => "truncated",
+        }
+    }
+}
+
+impl fmt::Display for DivergenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether a divergence can change behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceSeverity {
+    /// A reorder of causally independent events, or a label change.
+    Benign,
+    /// Anything that can change what the program observes.
+    Significant,
+}
+
+impl DivergenceSeverity {
+    /// Returns the stable snake-case name used in reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Benign => "benign",
+            Self::Significant => "significant",
+        }
+    }
+}
+
+impl fmt::Display for DivergenceSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Tuning for [`semantic_diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Events buffered per trace while looking for a moved or missing event.
+    /// Events moved farther than this show up as missing on both sides.
+    pub window: usize,
+    /// Divergences reported in full; later ones are only counted.
+    pub max_divergences: usize,
+    /// Events of trace A shown before each divergence.
+    pub context: usize,
+    /// Spawn-site labels of trace A, keyed by raw [`CompactTaskId`].
+    pub labels_a: BTreeMap<u64, String>,
+    /// Spawn-site labels of trace B, keyed by raw [`CompactTaskId`].
+    pub labels_b: BTreeMap<u64, String>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            window: 64,
+            max_divergences: 10,
+            context: 3,
+            labels_a: BTreeMap::new(),
+            labels_b: BTreeMap::new(),
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Sets the lookahead window per trace (at least 1).
+    #[must_use]
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Sets how many divergences are reported in full.
+    #[must_use]
+    pub const fn with_max_divergences(mut self, max: usize) -> Self {
+        self.max_divergences = max;
+        self
+    }
+
+    /// Sets how many preceding events are kept as context.
+    #[must_use]
+    pub const fn with_context(mut self, context: usize) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Attaches spawn-site labels to the spawns of each trace.
+    ///
+    /// Replay traces do not record labels; callers that know them (from the
+    /// observability trace or the test itself) can supply them here.
+    #[must_use]
+    pub fn with_spawn_labels(mut self, a: BTreeMap<u64, String>, b: BTreeMap<u64, String>) -> Self {
+        self.labels_a = a;
+        self.labels_b = b;
+        self
+    }
+
+    /// Labels the spawns of each trace with the spawn-site names resolved by
+    /// [`annotate`](crate::trace::annotate).
+    #[must_use]
+    pub fn with_annotations(self, a: &TraceAnnotations, b: &TraceAnnotations) -> Self {
+        self.with_spawn_labels(a.compact_labels(), b.compact_labels())
+    }
+}
+
+/// One event as the diff sees it, with ids replaced by structural names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlignedEvent {
+    /// Zero-based position in its trace.
+    pub index: u64,
+    /// Event kind, e.g. `TaskSpawned`.
+    pub kind: String,
+    /// Structural name of the task, region or resource the event concerns.
+    pub subject: Option<String>,
+    /// Compared payload, e.g. `outcome=0`; empty if there is none.
+    pub detail: String,
+    /// Spawn-site label of a `TaskSpawned` event, if one was supplied.
+    pub label: Option<String>,
+}
+
+impl fmt::Display for AlignedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {}", self.index, self.kind)?;
+        if let Some(subject) = &self.subject {
+            write!(f, " {subject}")?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, " [{label}]")?;
+        }
+        if !self.detail.is_empty() {
+            write!(f, " {}", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// A difference between the two traces.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// What differs.
+    pub kind: DivergenceKind,
+    /// Whether it can change behavior.
+    pub severity: DivergenceSeverity,
+    /// The event of trace A involved, if any.
+    pub a: Option<AlignedEvent>,
+    /// The event of trace B involved, if any.
+    pub b: Option<AlignedEvent>,
+    /// For [`DivergenceKind::Truncated`], how many events the longer trace
+    /// has left, starting at `a` or `b`; zero otherwise.
+    pub remaining: u64,
+    /// The events of trace A just before this divergence, oldest first.
+    pub context: Vec<AlignedEvent>,
+}
+
+/// Result of [`semantic_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceDiffReport {
+    /// [`TRACE_DIFF_SCHEMA_VERSION`] of this report.
+    pub schema_version: u32,
+    /// Events read from trace A.
+    pub events_a: u64,
+    /// Events read from trace B.
+    pub events_b: u64,
+    /// Events matched in order before the first divergence.
+    pub shared_prefix: u64,
+    /// Events paired across the traces, including reordered ones.
+    pub matched: u64,
+    /// Every divergence found.
+    pub total_divergences: u64,
+    /// How many of those are significant.
+    pub significant: u64,
+    /// How many of those are benign.
+    pub benign: u64,
+    /// Divergence counts by kind.
+    pub by_kind: BTreeMap<DivergenceKind, u64>,
+    /// The first [`DiffOptions::max_divergences`] divergences, in order.
+    pub divergences: Vec<Divergence>,
+    /// Most events buffered at once across both traces.
+    pub peak_buffered: usize,
+}
+
+impl TraceDiffReport {
+    /// True if the traces are semantically identical.
+    #[must_use]
+    pub const fn is_identical(&self) -> bool {
+        self.total_divergences == 0
+    }
+
+    /// True if any divergence can change behavior.
+    #[must_use]
+    pub const fn has_significant(&self) -> bool {
+        self.significant > 0
+    }
+}
+
+impl fmt::Display for TraceDiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "Traces are semantically identical ({} events)", self.matched);
+        }
+        writeln!(
+            f,
+            "{} divergence(s): {} significant, {} benign",
+            self.total_divergences, self.significant, self.benign
+        )?;
+        writeln!(
+            f,
+            "Shared prefix: {} events (A={}, B={}, matched {})",
+            self.shared_prefix, self.events_a, self.events_b, self.matched
+        )?;
+        let by_kind: Vec<String> = self
+            .by_kind
+            .iter()
+            .map(|(kind, count)| format!("{kind}={count}"))
+            .collect();
+        writeln!(f, "By kind: {}", by_kind.join(", "))?;
+        for (number, divergence) in self.divergences.iter().enumerate() {
+            writeln!(
+                f,
+                "Divergence {} of {}: {} ({})",
+                number + 1,
+                self.total_divergences,
+                divergence.kind,
+                divergence.severity
+            )?;
+            for event in &divergence.context {
+                writeln!(f, "     {event}")?;
+            }
+            let side = |event: Option<&AlignedEvent>| {
+                event.map_or_else(|| "<none>".to_string(), ToString::to_string)
+            };
+            writeln!(f, "  A: {}", side(divergence.a.as_ref()))?;
+            writeln!(f, "  B: {}", side(divergence.b.as_ref()))?;
+            if divergence.remaining > 0 {
+                writeln!(f, "  ({} more event(s) on one side)", divergence.remaining)?;
+            }
+        }
+        let unreported = self.total_divergences - self.divergences.len() as u64;
+        if unreported > 0 {
+            writeln!(f, "... {unreported} more divergence(s) not shown")?;
+        }
+        Ok(())
+    }
+}
+
+/// Semantically diffs two replay event streams.
+///
+/// Both streams are consumed once, in lockstep; see the
+/// [module documentation](self) for how events are named and aligned.
+#[must_use]
+pub fn semantic_diff<A, B>(a: A, b: B, options: &DiffOptions) -> TraceDiffReport
+where
+    A: IntoIterator<Item = ReplayEvent>,
+    B: IntoIterator<Item = ReplayEvent>,
+{
+    let mut a = Side::new(a.into_iter(), &options.labels_a);
+    let mut b = Side::new(b.into_iter(), &options.labels_b);
+    let mut differ = Differ::new(options);
+    differ.run(&mut a, &mut b);
+    differ.report.events_a = a.read;
+    differ.report.events_b = b.read;
+    differ.report
+}
+
+type Field = Option<(&'static str, i128)>;
+
+/// An event with ids replaced by structural names.
+#[derive(Debug, Clone)]
+struct Norm {
+    index: u64,
+    kind: &'static str,
+    subject: Option<Rc<str>>,
+    fields: [Field; 2],
+    label: Option<Rc<str>>,
+}
+
+impl Norm {
+    /// Labels are compared separately so a rename does not break pairing.
+    fn same(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.subject == other.subject && self.fields == other.fields
+    }
+
+    fn independent_of(&self, other: &Self) -> bool {
+        match (&self.subject, &other.subject) {
+            (Some(x), Some(y)) => !nested(x, y) && !nested(y, x),
+            _ => false,
+        }
+    }
+
+    fn aligned(&self) -> AlignedEvent {
+        let detail: Vec<String> = self
+            .fields
+            .iter()
+            .flatten()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        AlignedEvent {
+            index: self.index,
+            kind: self.kind.to_string(),
+            subject: self.subject.as_deref().map(str::to_string),
+            detail: detail.join(" "),
+            label: self.label.as_deref().map(str::to_string),
+        }
+    }
+}
+
+/// True if `outer` names `inner`, its region ancestry or its creation site.
+fn nested(outer: &str, inner: &str) -> bool {
+    inner
+        .strip_prefix(outer)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '/', '#']))
+}
+
+/// Hands out `prefix0`, `prefix1`, ... per prefix.
+#[derive(Debug, Default)]
+struct Ordinals(BTreeMap<String, u32>);
+
+impl Ordinals {
+    fn next(&mut self, prefix: String) -> Rc<str> {
+        let name = format!("{prefix}{}", self.0.get(&prefix).copied().unwrap_or(0));
+        *self.0.entry(prefix).or_default() += 1;
+        Rc::from(name)
+    }
+}
+
+fn name_of(
+    names: &mut BTreeMap<u64, Rc<str>>,
+    ordinals: &mut Ordinals,
+    id: u64,
+    prefix: impl FnOnce() -> String,
+) -> Rc<str> {
+    Rc::clone(names.entry(id).or_insert_with(|| ordinals.next(prefix())))
+}
+
+/// Maps one trace's ids to structural names.
+#[derive(Debug)]
+struct Namer<'l> {
+    labels: &'l BTreeMap<u64, String>,
+    regions: BTreeMap<u64, Rc<str>>,
+    tasks: BTreeMap<u64, Rc<str>>,
+    timers: BTreeMap<u64, Rc<str>>,
+    tokens: BTreeMap<u64, Rc<str>>,
+    ordinals: Ordinals,
+    /// The task that was scheduled last, the creation site of resources.
+    current: Option<Rc<str>>,
+}
+
+impl<'l> Namer<'l> {
+    fn new(labels: &'l BTreeMap<u64, String>) -> Self {
+        Self {
+            labels,
+            regions: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+            timers: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            ordinals: Ordinals::default(),
+            current: None,
+        }
+    }
+
+    fn region(&mut self, id: CompactRegionId) -> Rc<str> {
+        name_of(&mut self.regions, &mut self.ordinals, id.0, || "?r".into())
+    }
+
+    fn task(&mut self, id: CompactTaskId) -> Rc<str> {
+        name_of(&mut self.tasks, &mut self.ordinals, id.0, || "?t".into())
+    }
+
+    fn timer(&mut self, id: u64) -> Rc<str> {
+        name_of(&mut self.timers, &mut self.ordinals, id, || "?timer".into())
+    }
+
+    fn token(&mut self, token: u64) -> Rc<str> {
+        let site = self.site();
+        name_of(&mut self.tokens, &mut self.ordinals, token, || format!("{site}#io"))
+    }
+
+    fn site(&self) -> String {
+        self.current.as_deref().unwrap_or_default().to_string()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn normalize(&mut self, index: u64, event: &ReplayEvent) -> Norm {
+        let mut label = None;
+        let (kind, subject, fields): (_, _, [Field; 2]) = match *event {
+            ReplayEvent::TaskScheduled { task, .. } => {
+                let name = self.task(task);
+                self.current = Some(Rc::clone(&name));
+                ("TaskScheduled", Some(name), [None, None])
+            }
+            ReplayEvent::TaskYielded { task } => {
+                ("TaskYielded", Some(self.task(task)), [None, None])
+            }
+            ReplayEvent::TaskCompleted { task, outcome } => (
+                "TaskCompleted",
+                Some(self.task(task)),
+                [Some(("outcome", outcome.into())), None],
+            ),
+            ReplayEvent::TaskSpawned { task, region, .. } => {
+                let region = self.region(region);
+                let name = self.ordinals.next(format!("{region}/t"));
+                self.tasks.insert(task.0, Rc::clone(&name));
+                label = self.labels.get(&task.0).map(|label| Rc::from(label.as_str()));
+                ("TaskSpawned", Some(name), [None, None])
+            }
+            ReplayEvent::TimeAdvanced { .. } => ("TimeAdvanced", None, [None, None]),
+            ReplayEvent::TimerCreated { timer_id, .. } => {
+                let name = self.ordinals.next(format!("{}#timer", self.site()));
+                self.timers.insert(timer_id, Rc::clone(&name));
+                ("TimerCreated", Some(name), [None, None])
+            }
+            ReplayEvent::TimerFired { timer_id } => {
+                ("TimerFired", Some(self.timer(timer_id)), [None, None])
+            }
+            ReplayEvent::TimerCancelled { timer_id } => {
+                ("TimerCancelled", Some(self.timer(timer_id)), [None, None])
+            }
+            ReplayEvent::IoReady { token, readiness } => (
+                "IoReady",
+                Some(self.token(token)),
+                [Some(("readiness", readiness.into())), None],
+            ),
+            ReplayEvent::IoResult { token, bytes } => (
+                "IoResult",
+                Some(self.token(token)),
+                [Some(("bytes", bytes.into())), None],
+            ),
+            ReplayEvent::IoError { token, kind } => (
+                "IoError",
+                Some(self.token(token)),
+                [Some(("kind", kind.into())), None],
+            ),
+            ReplayEvent::RngSeed { seed } => ("RngSeed", None, [Some(("seed", seed.into())), None]),
+            ReplayEvent::RngValue { value } => {
+                ("RngValue", None, [Some(("value", value.into())), None])
+            }
+            ReplayEvent::ChaosInjection { kind, task, data } => (
+                "ChaosInjection",
+                task.map(|task| self.task(task)),
+                [Some(("kind", kind.into())), Some(("data", data.into()))],
+            ),
+            ReplayEvent::RegionCreated { region, parent, .. } => {
+                let prefix = match parent {
+                    Some(parent) => format!("{}.", self.region(parent)),
+                    None => "r".to_string(),
+                };
+                let name = self.ordinals.next(prefix);
+                self.regions.insert(region.0, Rc::clone(&name));
+                ("RegionCreated", Some(name), [None, None])
+            }
+            ReplayEvent::RegionClosed { region, outcome } => (
+                "RegionClosed",
+                Some(self.region(region)),
+                [Some(("outcome", outcome.into())), None],
+            ),
+            ReplayEvent::RegionCancelled {
+                region,
+                cancel_kind,
+            } => (
+                "RegionCancelled",
+                Some(self.region(region)),
+                [Some(("cancel_kind", cancel_kind.into())), None],
+            ),
+            ReplayEvent::WakerWake { task } => ("WakerWake", Some(self.task(task)), [None, None]),
+            ReplayEvent::WakerBatchWake { count } => {
+                ("WakerBatchWake", None, [Some(("count", count.into())), None])
+            }
+            ReplayEvent::Checkpoint {
+                active_tasks,
+                active_regions,
+                ..
+            } => (
+                "Checkpoint",
+                None,
+                [
+                    Some(("active_tasks", active_tasks.into())),
+                    Some(("active_regions", active_regions.into())),
+                ],
+            ),
+        };
+        Norm {
+            index,
+            kind,
+            subject,
+            fields,
+            label,
+        }
+    }
+}
+
+/// One trace: its remaining events and the lookahead window.
+struct Side<'l, I> {
+    events: I,
+    namer: Namer<'l>,
+    pending: VecDeque<Norm>,
+    read: u64,
+    exhausted: bool,
+}
+
+impl<'l, I: Iterator<Item = ReplayEvent>> Side<'l, I> {
+    fn new(events: I, labels: &'l BTreeMap<u64, String>) -> Self {
+        Self {
+            events,
+            namer: Namer::new(labels),
+            pending: VecDeque::new(),
+            read: 0,
+            exhausted: false,
+        }
+    }
+
+    fn fill(&mut self, window: usize) {
+        while !self.exhausted && self.pending.len() < window {
+            if let Some(event) = self.events.next() {
+                let norm = self.namer.normalize(self.read, &event);
+                self.read += 1;
+                self.pending.push_back(norm);
+            } else {
+                self.exhausted = true;
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Norm {
+        self.pending.pop_front().expect("head checked by caller")
+    }
+
+    /// Position of `target` in the window past the head.
+    fn find(&self, target: &Norm) -> Option<usize> {
+        self.pending
+            .iter()
+            .skip(1)
+            .position(|event| event.same(target))
+            .map(|position| position + 1)
+    }
+
+    /// Moves the event at `position` out of the window and reports whether
+    /// it is independent of every event it jumped over.
+    fn take_moved(&mut self, position: usize) -> (Norm, bool) {
+        let moved = self.pending.remove(position).expect("position in window");
+        let benign = self
+            .pending
+            .iter()
+            .take(position)
+            .all(|event| moved.independent_of(event));
+        (moved, benign)
+    }
+
+    /// Counts the events left, consuming the rest of the stream unnamed.
+    fn drain(&mut self) -> u64 {
+        let rest = self.events.by_ref().count() as u64;
+        self.read += rest;
+        let remaining = self.pending.len() as u64 + rest;
+        self.pending.clear();
+        remaining
+    }
+}
+
+struct Differ<'o> {
+    options: &'o DiffOptions,
+    report: TraceDiffReport,
+    /// The last events consumed from trace A.
+    context: VecDeque<Norm>,
+}
+
+impl<'o> Differ<'o> {
+    fn new(options: &'o DiffOptions) -> Self {
+        Self {
+            options,
+            report: TraceDiffReport {
+                schema_version: TRACE_DIFF_SCHEMA_VERSION,
+                ..TraceDiffReport::default()
+            },
+            context: VecDeque::new(),
+        }
+    }
+
+    fn run<A, B>(&mut self, a: &mut Side<'_, A>, b: &mut Side<'_, B>)
+    where
+        A: Iterator<Item = ReplayEvent>,
+        B: Iterator<Item = ReplayEvent>,
+    {
+        let window = self.options.window.max(1);
+        loop {
+            a.fill(window);
+            b.fill(window);
+            let buffered = a.pending.len() + b.pending.len();
+            self.report.peak_buffered = self.report.peak_buffered.max(buffered);
+            let (Some(head_a), Some(head_b)) = (a.pending.front(), b.pending.front()) else {
+                break;
+            };
+
+            if head_a.same(head_b) {
+                let (x, y) = (a.pop(), b.pop());
+                if x.label == y.label {
+                    if self.report.is_identical() {
+                        self.report.shared_prefix += 1;
+                    }
+                } else {
+                    self.record(DivergenceKind::Label, true, Some(&x), Some(&y), 0);
+                }
+                self.report.matched += 1;
+                self.consume(x);
+                continue;
+            }
+
+            match (a.find(head_b), b.find(head_a)) {
+                (Some(in_a), Some(in_b)) if in_b <= in_a => {
+                    let (y, benign) = b.take_moved(in_b);
+                    let x = a.pop();
+                    self.record(DivergenceKind::Reorder, benign, Some(&x), Some(&y), 0);
+                    self.report.matched += 1;
+                    self.consume(x);
+                }
+                (Some(in_a), Some(_)) => {
+                    let (x, benign) = a.take_moved(in_a);
+                    let y = b.pop();
+                    self.record(DivergenceKind::Reorder, benign, Some(&x), Some(&y), 0);
+                    self.report.matched += 1;
+                    self.consume(x);
+                }
+                (Some(_), None) => {
+                    let x = a.pop();
+                    self.record(DivergenceKind::OnlyInA, false, Some(&x), None, 0);
+                    self.consume(x);
+                }
+                (None, Some(_)) => {
+                    let y = b.pop();
+                    self.record(DivergenceKind::OnlyInB, false, None, Some(&y), 0);
+                }
+                (None, None) => {
+                    let (x, y) = (a.pop(), b.pop());
+                    let kind = if x.kind == y.kind {
+                        DivergenceKind::Payload
+                    } else {
+                        DivergenceKind::Kind
+                    };
+                    self.record(kind, false, Some(&x), Some(&y), 0);
+                    self.consume(x);
+                }
+            }
+        }
+
+        if let Some(first) = a.pending.front().cloned() {
+            let remaining = a.drain();
+            self.record(DivergenceKind::Truncated, false, Some(&first), None, remaining);
+        }
+        if let Some(first) = b.pending.front().cloned() {
+            let remaining = b.drain();
+            self.record(DivergenceKind::Truncated, false, None, Some(&first), remaining);
+        }
+    }
+
+    fn consume(&mut self, event: Norm) {
+        if self.options.context == 0 {
+            return;
+        }
+        if self.context.len() == self.options.context {
+            self.context.pop_front();
+        }
+        self.context.push_back(event);
+    }
+
+    fn record(
+        &mut self,
+        kind: DivergenceKind,
+        benign: bool,
+        a: Option<&Norm>,
+        b: Option<&Norm>,
+        remaining: u64,
+    ) {
+        let severity = if benign {
+            self.report.benign += 1;
+            DivergenceSeverity::Benign
+        } else {
+            self.report.significant += 1;
+            DivergenceSeverity::Significant
+        };
+        self.report.total_divergences += 1;
+        *self.report.by_kind.entry(kind).or_default() += 1;
+        if self.report.divergences.len() < self.options.max_divergences {
+            self.report.divergences.push(Divergence {
+                kind,
+                severity,
+                a: a.map(Norm::aligned),
+                b: b.map(Norm::aligned),
+                remaining,
+                context: self.context.iter().map(Norm::aligned).collect(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::{LabConfig, LabRuntime};
+    use crate::types::Budget;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn task(n: u64) -> CompactTaskId {
+        CompactTaskId(n)
+    }
+
+    fn region(n: u64) -> CompactRegionId {
+        CompactRegionId(n)
+    }
+
+    /// Two tasks in one region: T1 arms a timer, T2 does some I/O, both are
+    /// woken and complete. `base` shifts every id and `scale` every tick, as
+    /// a rerun after an unrelated change would.
+    fn scenario(base: u64, scale: u64) -> Vec<ReplayEvent> {
+        let r = region(base);
+        let (t1, t2) = (task(base + 1), task(base + 2));
+        let (timer, token) = (base + 7, base + 9);
+        vec![
+            ReplayEvent::RegionCreated {
+                region: r,
+                parent: None,
+                at_tick: 0,
+            },
+            ReplayEvent::TaskSpawned {
+                task: t1,
+                region: r,
+                at_tick: scale,
+            },
+            ReplayEvent::TaskSpawned {
+                task: t2,
+                region: r,
+                at_tick: scale,
+            },
+            ReplayEvent::TaskScheduled {
+                task: t1,
+                at_tick: 2 * scale,
+            },
+            ReplayEvent::TimerCreated {
+                timer_id: timer,
+                deadline_nanos: 5 * scale,
+            },
+            ReplayEvent::TaskYielded { task: t1 },
+            ReplayEvent::TaskScheduled {
+                task: t2,
+                at_tick: 3 * scale,
+            },
+            ReplayEvent::IoReady {
+                token,
+                readiness: 1,
+            },
+            ReplayEvent::TaskYielded { task: t2 },
+            ReplayEvent::TimeAdvanced {
+                from_nanos: 0,
+                to_nanos: 5 * scale,
+            },
+            ReplayEvent::TimerFired { timer_id: timer },
+            ReplayEvent::IoResult { token, bytes: 128 },
+            ReplayEvent::WakerWake { task: t1 },
+            ReplayEvent::WakerWake { task: t2 },
+            ReplayEvent::TaskScheduled {
+                task: t1,
+                at_tick: 6 * scale,
+            },
+            ReplayEvent::TaskCompleted {
+                task: t1,
+                outcome: 0,
+            },
+            ReplayEvent::TaskScheduled {
+                task: t2,
+                at_tick: 7 * scale,
+            },
+            ReplayEvent::TaskCompleted {
+                task: t2,
+                outcome: 0,
+            },
+            ReplayEvent::RegionClosed {
+                region: r,
+                outcome: 0,
+            },
+        ]
+    }
+
+    fn swapped(mut events: Vec<ReplayEvent>, at: usize) -> Vec<ReplayEvent> {
+        events.swap(at, at + 1);
+        events
+    }
+
+    fn lab_trace(seed: u64) -> Vec<ReplayEvent> {
+        let mut runtime = LabRuntime::new(LabConfig::new(seed).with_default_replay_recording());
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        for _ in 0..3 {
+            let (task, _) = runtime
+                .state
+                .create_task(region, Budget::INFINITE, async {})
+                .expect("create task");
+            runtime.scheduler.lock().schedule(task, 0);
+        }
+        runtime.run_until_quiescent();
+        runtime
+            .finish_replay_trace()
+            .expect("replay recording enabled")
+            .events
+    }
+
+    #[test]
+    fn identical_seed_traces_diff_empty() {
+        init_test("identical_seed_traces_diff_empty");
+        let (first, second) = (lab_trace(7), lab_trace(7));
+        crate::assert_with_log!(!first.is_empty(), "trace recorded", true, !first.is_empty());
+        let report = semantic_diff(first.clone(), second, &DiffOptions::default());
+        crate::assert_with_log!(
+            report.is_identical(),
+            "same seed diffs empty",
+            0,
+            report.total_divergences
+        );
+        crate::assert_with_log!(
+            report.shared_prefix == first.len() as u64,
+            "whole trace shared",
+            first.len(),
+            report.shared_prefix
+        );
+
+        // Fresh ids and stretched ticks are not divergences.
+        let report = semantic_diff(scenario(0, 1), scenario(1000, 3), &DiffOptions::default());
+        crate::assert_with_log!(
+            report.is_identical(),
+            "shifted ids diff empty",
+            0,
+            report.total_divergences
+        );
+        crate::test_complete!("identical_seed_traces_diff_empty");
+    }
+
+    #[test]
+    fn single_reorder_is_localized() {
+        init_test("single_reorder_is_localized");
+        // The timer firing and T2's I/O result are independent.
+        let report = semantic_diff(
+            scenario(0, 1),
+            swapped(scenario(50, 1), 10),
+            &DiffOptions::default(),
+        );
+        crate::assert_with_log!(
+            report.total_divergences == 1,
+            "one divergence",
+            1,
+            report.total_divergences
+        );
+        let divergence = &report.divergences[0];
+        let a = divergence.a.as_ref().expect("event in A");
+        let b = divergence.b.as_ref().expect("event in B");
+        crate::assert_with_log!(
+            divergence.kind == DivergenceKind::Reorder,
+            "reported as reorder",
+            DivergenceKind::Reorder,
+            divergence.kind
+        );
+        crate::assert_with_log!(
+            divergence.severity == DivergenceSeverity::Benign,
+            "independent events reorder benignly",
+            DivergenceSeverity::Benign,
+            divergence.severity
+        );
+        crate::assert_with_log!(
+            (a.index, b.index) == (10, 11),
+            "localized to the swapped pair",
+            (10, 11),
+            (a.index, b.index)
+        );
+        crate::assert_with_log!(
+            a.subject.as_deref() == Some("r0/t0#timer0"),
+            "timer named by creation site",
+            "r0/t0#timer0",
+            &a.subject
+        );
+        crate::assert_with_log!(
+            report.shared_prefix == 10,
+            "shared prefix ends at the swap",
+            10,
+            report.shared_prefix
+        );
+        crate::assert_with_log!(
+            divergence.context.len() == 3 && divergence.context[2].index == 9,
+            "context precedes the swap",
+            9,
+            &divergence.context
+        );
+
+        // The timer cannot fire before time advances to its deadline.
+        let report = semantic_diff(
+            scenario(0, 1),
+            swapped(scenario(0, 1), 9),
+            &DiffOptions::default(),
+        );
+        let divergence = &report.divergences[0];
+        crate::assert_with_log!(
+            report.total_divergences == 1
+                && divergence.severity == DivergenceSeverity::Significant,
+            "causal reorder is significant",
+            DivergenceSeverity::Significant,
+            divergence.severity
+        );
+        crate::test_complete!("single_reorder_is_localized");
+    }
+
+    fn labeled(t1: &str, t2: &str, base: u64) -> BTreeMap<u64, String> {
+        BTreeMap::from([(base + 1, t1.to_string()), (base + 2, t2.to_string())])
+    }
+
+    fn renamed_label_report() -> TraceDiffReport {
+        let options = DiffOptions::default().with_spawn_labels(
+            labeled("fetch_loop", "writer", 0),
+            labeled("poll_upstream", "writer", 100),
+        );
+        semantic_diff(scenario(0, 1), scenario(100, 2), &options)
+    }
+
+    #[test]
+    fn renamed_spawn_label_is_one_label_divergence() {
+        init_test("renamed_spawn_label_is_one_label_divergence");
+        let report = renamed_label_report();
+        crate::assert_with_log!(
+            report.total_divergences == 1,
+            "rename does not cascade",
+            1,
+            report.total_divergences
+        );
+        let divergence = &report.divergences[0];
+        crate::assert_with_log!(
+            divergence.kind == DivergenceKind::Label
+                && divergence.severity == DivergenceSeverity::Benign,
+            "reported as a benign label change",
+            DivergenceKind::Label,
+            divergence.kind
+        );
+        let labels = (
+            divergence.a.as_ref().and_then(|e| e.label.as_deref()),
+            divergence.b.as_ref().and_then(|e| e.label.as_deref()),
+        );
+        crate::assert_with_log!(
+            labels == (Some("fetch_loop"), Some("poll_upstream")),
+            "both labels shown",
+            (Some("fetch_loop"), Some("poll_upstream")),
+            labels
+        );
+        crate::assert_with_log!(
+            report.matched == 19 && report.shared_prefix == 1,
+            "every event still pairs",
+            (19, 1),
+            (report.matched, report.shared_prefix)
+        );
+        crate::test_complete!("renamed_spawn_label_is_one_label_divergence");
+    }
+
+    #[test]
+    fn different_lengths_report_truncation() {
+        init_test("different_lengths_report_truncation");
+        let full = scenario(0, 1);
+        let report = semantic_diff(full[..12].to_vec(), full, &DiffOptions::default());
+        crate::assert_with_log!(
+            report.total_divergences == 1 && report.shared_prefix == 12,
+            "one truncation after the shared prefix",
+            (1, 12),
+            (report.total_divergences, report.shared_prefix)
+        );
+        let divergence = &report.divergences[0];
+        crate::assert_with_log!(
+            divergence.kind == DivergenceKind::Truncated && divergence.remaining == 7,
+            "B's tail summarized",
+            7,
+            divergence.remaining
+        );
+        crate::assert_with_log!(
+            (report.events_a, report.events_b) == (12, 19),
+            "lengths reported",
+            (12, 19),
+            (report.events_a, report.events_b)
+        );
+        crate::test_complete!("different_lengths_report_truncation");
+    }
+
+    #[test]
+    fn json_schema_is_stable() {
+        init_test("json_schema_is_stable");
+        let value = serde_json::to_value(renamed_label_report()).expect("serialize");
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .expect("object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        let expected = [
+            "benign",
+            "by_kind",
+            "divergences",
+            "events_a",
+            "events_b",
+            "matched",
+            "peak_buffered",
+            "schema_version",
+            "shared_prefix",
+            "significant",
+            "total_divergences",
+        ];
+        crate::assert_with_log!(keys == expected, "report keys", expected, keys);
+        crate::assert_with_log!(
+            value["schema_version"] == TRACE_DIFF_SCHEMA_VERSION,
+            "schema version",
+            TRACE_DIFF_SCHEMA_VERSION,
+            &value["schema_version"]
+        );
+        let expected_divergence = serde_json::json!({
+            "kind": "label",
+            "severity": "benign",
+            "a": {
+                "index": 1,
+                "kind": "TaskSpawned",
+                "subject": "r0/t0",
+                "detail": "",
+                "label": "fetch_loop"
+            },
+            "b": {
+                "index": 1,
+                "kind": "TaskSpawned",
+                "subject": "r0/t0",
+                "detail": "",
+                "label": "poll_upstream"
+            },
+            "remaining": 0,
+            "context": [{
+                "index": 0,
+                "kind": "RegionCreated",
+                "subject": "r0",
+                "detail": "",
+                "label": null
+            }]
+        });
+        crate::assert_with_log!(
+            value["divergences"][0] == expected_divergence,
+            "divergence shape",
+            &expected_divergence,
+            &value["divergences"][0]
+        );
+        crate::assert_with_log!(
+            value["by_kind"] == serde_json::json!({ "label": 1 }),
+            "counts keyed by kind name",
+            r#"{"label":1}"#,
+            &value["by_kind"]
+        );
+        crate::test_complete!("json_schema_is_stable");
+    }
+
+    #[test]
+    fn long_traces_diff_in_bounded_memory() {
+        init_test("long_traces_diff_in_bounded_memory");
+        const LEN: u64 = 2_000_000;
+        const SWAP: u64 = 1_500_000;
+        let events = |swap: bool| {
+            (0..LEN).map(move |i| {
+                let value = match i {
+                    SWAP if swap => SWAP + 1,
+                    j if j == SWAP + 1 && swap => SWAP,
+                    j => j,
+                };
+                ReplayEvent::RngValue { value }
+            })
+        };
+        let options = DiffOptions::default().with_window(16);
+        let report = semantic_diff(events(false), events(true), &options);
+        crate::assert_with_log!(
+            report.total_divergences == 1 && report.shared_prefix == SWAP,
+            "single swap found deep in the trace",
+            (1, SWAP),
+            (report.total_divergences, report.shared_prefix)
+        );
+        crate::assert_with_log!(
+            report.peak_buffered <= 2 * 16,
+            "buffering bounded by the window",
+            32,
+            report.peak_buffered
+        );
+        crate::assert_with_log!(
+            report.events_a == LEN && report.events_b == LEN,
+            "both traces fully read",
+            LEN,
+            report.events_a
+        );
+        crate::test_complete!("long_traces_diff_in_bounded_memory");
+    }
+}
@@ -4,7 +4,10 @@
 //! allowing efficient capture without unbounded memory growth.
 
 use super::event::TraceEvent;
+use super::symbols::SpawnSiteId;
+use crate::types::TaskId;
 use parking_lot::Mutex;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -130,7 +133,9 @@ impl Default for TraceBuffer {
 /// Thread-safe handle for sharing a trace buffer across tasks.
 ///
 /// This wraps a [`TraceBuffer`] in a mutex and adds a monotonically increasing
-/// sequence counter for event ordering.
+/// sequence counter for event ordering. A sidecar keeps the spawn site of the
+/// most recent tasks (as many as the buffer holds events) for
+/// [`annotate`](super::symbols::annotate).
 #[derive(Debug, Clone)]
 pub struct TraceBufferHandle {
     inner: Arc<TraceBufferInner>,
@@ -141,6 +146,7 @@ struct TraceBufferInner {
     buffer: Mutex<TraceBuffer>,
     next_seq: AtomicU64,
     total_pushed: AtomicU64,
+    spawn_sites: Mutex<VecDeque<(TaskId, SpawnSiteId)>>,
 }
 
 impl TraceBufferHandle {
@@ -152,6 +158,7 @@ impl TraceBufferHandle {
                 buffer: Mutex::new(TraceBuffer::new(capacity)),
                 next_seq: AtomicU64::new(0),
                 total_pushed: AtomicU64::new(0),
+                spawn_sites: Mutex::new(VecDeque::new()),
             }),
         }
     }
@@ -232,6 +239,27 @@ impl TraceBufferHandle {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Records where `task` was spawned.
+    ///
+    /// The sidecar holds at most [`capacity`](Self::capacity) sites; the
+    /// oldest is dropped first, like the events it describes.
+    pub fn record_spawn_site(&self, task: TaskId, site: SpawnSiteId) {
+        let capacity = self.capacity().max(1);
+        let mut sites = self.inner.spawn_sites.lock();
+        while sites.len() >= capacity {
+            sites.pop_front();
+        }
+        sites.push_back((task, site));
+    }
+
+    /// Returns the recorded spawn sites keyed by task.
+    ///
+    /// A task id reused by a later spawn maps to the later site.
+    #[must_use]
+    pub fn spawn_sites(&self) -> BTreeMap<TaskId, SpawnSiteId> {
+        self.inner.spawn_sites.lock().iter().copied().collect()
+    }
 }
 
 #[cfg(test)]
@@ -487,4 +515,17 @@ mod tests {
         let seqs: Vec<_> = handle.snapshot().iter().map(|event| event.seq).collect();
         assert_eq!(seqs, vec![0, 1]);
     }
+
+    #[test]
+    fn trace_buffer_handle_spawn_sites_are_bounded() {
+        let handle = TraceBufferHandle::new(2);
+        let site = |line| SpawnSiteId::of("src/lib.rs", line);
+        for n in 1..=3 {
+            handle.record_spawn_site(TaskId::new_for_test(n, 0), site(n));
+        }
+        let sites = handle.spawn_sites();
+        assert_eq!(sites.len(), 2);
+        assert!(!sites.contains_key(&TaskId::new_for_test(1, 0)));
+        assert_eq!(sites.get(&TaskId::new_for_test(3, 0)), Some(&site(3)));
+    }
 }
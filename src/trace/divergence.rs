@@ -1271,6 +1271,8 @@ fn summarize_event(event: &ReplayEvent) -> (String, String, Option<u64>, Option<
                 2 => "io_error",
                 3 => "wakeup_storm",
                 4 => "budget",
+                5 => "pause",
+                6 => "resume",
                 _ => "unknown",
             };
             (
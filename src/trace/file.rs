@@ -6,7 +6,7 @@
 //! - **Compactness**: Uses MessagePack for efficient binary encoding
 //! - **Versioning**: Format version in header for forward compatibility
 //! - **Streaming**: Events can be read incrementally without loading all into memory
-//! - **Compression**: Optional LZ4 compression for reduced storage (feature-gated)
+//! - **Compression**: Optional LZ4 or framed zstd compression (feature-gated)
 //!
 //! # File Format
 //!
@@ -16,9 +16,11 @@
 //! +-------------------+
 //! | Version (2 bytes) |  u16 little-endian
 //! +-------------------+
-//! | Flags (2 bytes)   |  u16 little-endian (bit 0 = compressed)
+//! | Flags (2 bytes)   |  u16 little-endian (bit 0 = compressed, bit 1 = dictionary)
 //! +-------------------+
-//! | Compression (1 b) |  u8 (0=none, 1=lz4)
+//! | Compression (1 b) |  u8 (0=none, 1=lz4, 2=zstd frames)
+//! +-------------------+
+//! | Dict id/crc (8 b) |  u32 + u32 little-endian (zstd frames only)
 //! +-------------------+
 //! | Meta len (4 bytes)|  u32 little-endian
 //! +-------------------+
@@ -28,6 +30,8 @@
 //! +-------------------+
 //! | Events (msgpack)  |  [ReplayEvent] length-prefixed (optionally compressed)
 //! +-------------------+
+//! | Frame index       |  zstd frames only (see the `framed` module)
+//! +-------------------+
 //! ```
 //!
 //! # Compression
@@ -36,6 +40,13 @@
 //! compressed in chunks using LZ4 for efficient streaming compression/decompression.
 //! Compression is auto-detected on read based on the flags in the header.
 //!
+//! `CompressionMode::Zstd` instead writes independently compressed zstd frames
+//! with a per-frame checksum and a footer index, so readers can seek to a frame
+//! and (in tolerant mode) skip a damaged frame without losing the rest of the
+//! trace. An optional `TraceDictionary` trained from representative traces
+//! improves compression of small frames; readers must be given the same
+//! dictionary via [`TraceReadOptions`].
+//!
 //! # Example
 //!
 //! ```ignore
@@ -58,6 +69,11 @@
 //! }
 //! ```
 
+#[cfg(feature = "trace-compression")]
+use super::framed::{
+    DEFAULT_ZSTD_LEVEL, FrameDecoder, FrameEncoder, FrameIndexEntry, FrameLoss, TraceDictionary,
+    read_frame_index,
+};
 use super::recorder::{DEFAULT_MAX_FILE_SIZE, LimitAction, LimitKind, LimitReached};
 use super::replay::{REPLAY_SCHEMA_VERSION, ReplayEvent, TraceMetadata};
 use crate::tracing_compat::{error, warn};
@@ -74,14 +90,25 @@ pub const TRACE_MAGIC: &[u8; 11] = b"ASUPERTRACE";
 
 /// Current file format version.
 /// Version 2 adds compression byte after flags.
-pub const TRACE_FILE_VERSION: u16 = 2;
+/// Version 3 adds zstd frames with a dictionary descriptor and frame index.
+pub const TRACE_FILE_VERSION: u16 = 3;
+
+/// Version written for traces that do not use zstd frames, so they stay
+/// readable by version-2 readers.
+const UNFRAMED_FILE_VERSION: u16 = 2;
 
-/// Flag: Events are LZ4 compressed.
+/// Flag: Events are compressed (codec given by the compression byte).
 pub const FLAG_COMPRESSED: u16 = 0x0001;
 
+/// Flag: zstd frames were compressed with a dictionary.
+pub const FLAG_DICTIONARY: u16 = 0x0002;
+
 /// Header size (magic + version + flags + compression + meta_len).
 pub const HEADER_SIZE: usize = 11 + 2 + 2 + 1 + 4;
 
+/// Extra header bytes for zstd-framed traces (dictionary id + checksum).
+pub const FRAMED_HEADER_EXT_SIZE: usize = 4 + 4;
+
 /// Default chunk size for streaming compression (64KB).
 pub const DEFAULT_COMPRESSION_CHUNK_SIZE: usize = 64 * 1024;
 
@@ -135,7 +162,7 @@ fn is_disk_full_os_error(code: Option<i32>) -> bool {
     }
 }
 
-fn validate_event_len(len: usize) -> TraceFileResult<()> {
+pub(crate) fn validate_event_len(len: usize) -> TraceFileResult<()> {
     if len > MAX_EVENT_LEN {
         return Err(TraceFileError::OversizedField {
             field: "event_len",
@@ -147,7 +174,7 @@ fn validate_event_len(len: usize) -> TraceFileResult<()> {
     Ok(())
 }
 
-fn truncated_or_io(err: io::Error) -> TraceFileError {
+pub(crate) fn truncated_or_io(err: io::Error) -> TraceFileError {
     if err.kind() == io::ErrorKind::UnexpectedEof {
         TraceFileError::Truncated
     } else {
@@ -181,6 +208,19 @@ pub enum CompressionMode {
     /// Compresses if estimated size exceeds 1MB.
     #[cfg(feature = "trace-compression")]
     Auto,
+
+    /// Framed zstd compression.
+    ///
+    /// Events are batched into frames of roughly
+    /// [`TraceFileConfig::chunk_size`] bytes, each compressed independently
+    /// and listed in a footer index so readers can seek to any frame. Pair
+    /// with [`TraceFileConfig::with_dictionary`] for traces of many tiny
+    /// events.
+    #[cfg(feature = "trace-compression")]
+    Zstd {
+        /// Compression level (1 to 22, default 3).
+        level: i32,
+    },
 }
 
 impl CompressionMode {
@@ -190,7 +230,22 @@ impl CompressionMode {
         match self {
             Self::None => false,
             #[cfg(feature = "trace-compression")]
-            Self::Lz4 { .. } | Self::Auto => true,
+            Self::Lz4 { .. } | Self::Auto | Self::Zstd { .. } => true,
+        }
+    }
+
+    /// Returns true if this mode writes zstd frames.
+    #[must_use]
+    pub fn is_framed(&self) -> bool {
+        #[cfg(feature = "trace-compression")]
+        {
+            matches!(self, Self::Zstd { .. })
+        }
+
+        #[cfg(not(feature = "trace-compression"))]
+        {
+            let _ = self;
+            false
         }
     }
 
@@ -200,6 +255,8 @@ impl CompressionMode {
             Self::None => 0,
             #[cfg(feature = "trace-compression")]
             Self::Lz4 { .. } | Self::Auto => 1,
+            #[cfg(feature = "trace-compression")]
+            Self::Zstd { .. } => 2,
         }
     }
 
@@ -210,8 +267,12 @@ impl CompressionMode {
             0 => Some(Self::None),
             #[cfg(feature = "trace-compression")]
             1 => Some(Self::Lz4 { level: 1 }),
+            #[cfg(feature = "trace-compression")]
+            2 => Some(Self::Zstd {
+                level: DEFAULT_ZSTD_LEVEL,
+            }),
             #[cfg(not(feature = "trace-compression"))]
-            1 => None, // Compressed but feature not enabled
+            1 | 2 => None, // Compressed but feature not enabled
             _ => None,
         }
     }
@@ -224,8 +285,14 @@ pub struct TraceFileConfig {
     pub compression: CompressionMode,
 
     /// Chunk size for streaming compression (default: 64KB).
+    ///
+    /// For zstd frames this is the target frame size.
     pub chunk_size: usize,
 
+    /// Dictionary for [`CompressionMode::Zstd`] frames.
+    #[cfg(feature = "trace-compression")]
+    pub dictionary: Option<TraceDictionary>,
+
     /// Maximum events to write before stopping.
     /// Default: None (unlimited).
     pub max_events: Option<u64>,
@@ -243,6 +310,8 @@ impl Default for TraceFileConfig {
         Self {
             compression: CompressionMode::None,
             chunk_size: DEFAULT_COMPRESSION_CHUNK_SIZE,
+            #[cfg(feature = "trace-compression")]
+            dictionary: None,
             max_events: None,
             max_file_size: DEFAULT_MAX_FILE_SIZE,
             on_limit: LimitAction::StopRecording,
@@ -271,6 +340,17 @@ impl TraceFileConfig {
         self
     }
 
+    /// Sets the dictionary used to compress zstd frames.
+    ///
+    /// Only [`CompressionMode::Zstd`] uses the dictionary; readers must open
+    /// the trace with the same dictionary.
+    #[cfg(feature = "trace-compression")]
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: TraceDictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
     /// Sets a maximum number of events to write.
     #[must_use]
     pub const fn with_max_events(mut self, max_events: Option<u64>) -> Self {
@@ -384,6 +464,59 @@ pub enum TraceFileError {
         /// Maximum allowed.
         max: u64,
     },
+
+    /// The trace was compressed with a dictionary but none was supplied.
+    #[error(
+        "trace requires zstd dictionary {id:#010x} (checksum {checksum:#010x}); \
+         open it with TraceReadOptions::with_dictionary"
+    )]
+    DictionaryRequired {
+        /// Dictionary id recorded in the header.
+        id: u32,
+        /// Dictionary checksum recorded in the header.
+        checksum: u32,
+    },
+
+    /// The supplied dictionary is not the one the trace was compressed with.
+    #[error(
+        "zstd dictionary mismatch: trace was compressed with dictionary {expected_id:#010x} \
+         (checksum {expected_checksum:#010x}) but dictionary {found_id:#010x} \
+         (checksum {found_checksum:#010x}) was supplied"
+    )]
+    DictionaryMismatch {
+        /// Dictionary id recorded in the header.
+        expected_id: u32,
+        /// Dictionary checksum recorded in the header.
+        expected_checksum: u32,
+        /// Id of the supplied dictionary.
+        found_id: u32,
+        /// Checksum of the supplied dictionary.
+        found_checksum: u32,
+    },
+
+    /// A decompressed frame does not match its recorded checksum.
+    #[error("frame {frame} checksum mismatch: expected {expected:#010x}, found {actual:#010x}")]
+    FrameChecksumMismatch {
+        /// Zero-based frame number.
+        frame: u64,
+        /// Checksum recorded in the frame header.
+        expected: u32,
+        /// Checksum of the decompressed payload.
+        actual: u32,
+    },
+
+    /// The trace has no frame index to seek with.
+    #[error("trace has no frame index (not zstd-framed, or the writer did not finish)")]
+    FrameIndexMissing,
+
+    /// A frame number past the end of the frame index.
+    #[error("frame {frame} out of range: trace has {frames} frames")]
+    FrameOutOfRange {
+        /// Requested frame.
+        frame: u64,
+        /// Number of frames in the trace.
+        frames: u64,
+    },
 }
 
 impl From<rmp_serde::encode::Error> for TraceFileError {
@@ -442,6 +575,12 @@ pub struct TraceWriter {
     /// Buffer for uncompressed event data (used in chunked compression).
     #[cfg(feature = "trace-compression")]
     event_buffer: Vec<u8>,
+    /// Number of events in `event_buffer`.
+    #[cfg(feature = "trace-compression")]
+    buffered_events: u32,
+    /// Frame compressor and index for [`CompressionMode::Zstd`].
+    #[cfg(feature = "trace-compression")]
+    frame_encoder: Option<FrameEncoder>,
 }
 
 impl TraceWriter {
@@ -458,11 +597,20 @@ impl TraceWriter {
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be created.
+    /// Returns an error if the file cannot be created or the zstd compressor
+    /// rejects the configured level or dictionary.
     pub fn create_with_config(
         path: impl AsRef<Path>,
         config: TraceFileConfig,
     ) -> TraceFileResult<Self> {
+        #[cfg(feature = "trace-compression")]
+        let frame_encoder = match config.compression {
+            CompressionMode::Zstd { level } => {
+                Some(FrameEncoder::new(level, config.dictionary.as_ref())?)
+            }
+            _ => None,
+        };
+
         let file = File::create(path)?;
         let writer = BufWriter::new(file);
 
@@ -479,6 +627,10 @@ impl TraceWriter {
             halted: false,
             #[cfg(feature = "trace-compression")]
             event_buffer: Vec::new(),
+            #[cfg(feature = "trace-compression")]
+            buffered_events: 0,
+            #[cfg(feature = "trace-compression")]
+            frame_encoder,
         })
     }
 
@@ -618,11 +770,30 @@ impl TraceWriter {
         let meta_bytes = rmp_serde::to_vec(metadata)?;
 
         // Determine flags
-        let flags = if self.config.compression.is_compressed() {
+        let framed = self.config.compression.is_framed();
+        let mut flags = if self.config.compression.is_compressed() {
             FLAG_COMPRESSED
         } else {
             0
         };
+        let version = if framed {
+            TRACE_FILE_VERSION
+        } else {
+            UNFRAMED_FILE_VERSION
+        };
+        // Dictionary descriptor (id, checksum) for framed traces.
+        #[cfg(feature = "trace-compression")]
+        let dictionary = self
+            .config
+            .dictionary
+            .as_ref()
+            .filter(|_| framed)
+            .map(|dict| (dict.id(), dict.checksum()));
+        #[cfg(not(feature = "trace-compression"))]
+        let dictionary: Option<(u32, u32)> = None;
+        if dictionary.is_some() {
+            flags |= FLAG_DICTIONARY;
+        }
 
         // Once header emission starts, a failure leaves the file in a partial
         // state. Poison the writer so callers do not append events to a broken
@@ -631,9 +802,16 @@ impl TraceWriter {
 
         // Write header
         self.write_bytes(TRACE_MAGIC)?;
-        self.write_bytes(&TRACE_FILE_VERSION.to_le_bytes())?;
+        self.write_bytes(&version.to_le_bytes())?;
         self.write_bytes(&flags.to_le_bytes())?;
         self.write_bytes(&[self.config.compression.to_byte()])?; // compression byte
+        let mut header_size = HEADER_SIZE;
+        if framed {
+            let (dict_id, dict_checksum) = dictionary.unwrap_or_default();
+            self.write_bytes(&dict_id.to_le_bytes())?;
+            self.write_bytes(&dict_checksum.to_le_bytes())?;
+            header_size += FRAMED_HEADER_EXT_SIZE;
+        }
 
         // Write metadata length and data
         let meta_len = u32::try_from(meta_bytes.len()).map_err(|_| {
@@ -649,7 +827,7 @@ impl TraceWriter {
         self.write_bytes(&meta_bytes)?;
 
         // Reserve the event-count header slot; finish() backpatches it.
-        self.event_count_pos = HEADER_SIZE as u64 + u64::from(meta_len);
+        self.event_count_pos = header_size as u64 + u64::from(meta_len);
         self.write_bytes(&0u64.to_le_bytes())?;
         self.metadata_state = TraceWriterMetadataState::Written;
 
@@ -725,6 +903,7 @@ impl TraceWriter {
             self.event_buffer.extend_from_slice(&len.to_le_bytes());
             self.event_buffer.extend_from_slice(&event_bytes);
             self.buffered_bytes = self.buffered_bytes.saturating_add(estimated_bytes);
+            self.buffered_events += 1;
             self.event_count += 1;
 
             // Flush chunk if buffer exceeds threshold
@@ -748,6 +927,17 @@ impl TraceWriter {
             return Ok(());
         }
 
+        if let Some(encoder) = self.frame_encoder.as_mut() {
+            // The frame starts at the current end of file.
+            let frame =
+                encoder.encode(&self.event_buffer, self.buffered_events, self.bytes_written)?;
+            self.write_bytes(&frame)?;
+            self.event_buffer.clear();
+            self.buffered_events = 0;
+            self.buffered_bytes = 0;
+            return Ok(());
+        }
+
         // Compress the buffer
         let compressed = lz4_flex::compress_prepend_size(&self.event_buffer);
 
@@ -765,10 +955,23 @@ impl TraceWriter {
         self.write_bytes(&compressed)?;
 
         self.event_buffer.clear();
+        self.buffered_events = 0;
         self.buffered_bytes = 0;
         Ok(())
     }
 
+    /// Appends the frame index footer for zstd-framed traces.
+    ///
+    /// Runs at most once; later calls are no-ops.
+    #[cfg(feature = "trace-compression")]
+    fn write_frame_index(&mut self) -> TraceFileResult<()> {
+        let Some(encoder) = self.frame_encoder.take() else {
+            return Ok(());
+        };
+        let footer = encoder.encode_footer(self.bytes_written);
+        self.write_bytes(&footer)
+    }
+
     /// Finishes writing the trace file.
     ///
     /// This flushes any remaining compressed data, updates the event count
@@ -786,6 +989,7 @@ impl TraceWriter {
         #[cfg(feature = "trace-compression")]
         if self.config.compression.is_compressed() {
             self.flush_compressed_chunk()?;
+            self.write_frame_index()?;
         }
 
         if self.halted {
@@ -816,6 +1020,9 @@ impl Drop for TraceWriter {
             #[cfg(feature = "trace-compression")]
             if self.config.compression.is_compressed() {
                 let _ = self.flush_compressed_chunk();
+                if self.metadata_state == TraceWriterMetadataState::Written {
+                    let _ = self.write_frame_index();
+                }
             }
 
             // Best-effort: try to flush but don't panic
@@ -831,6 +1038,44 @@ impl Drop for TraceWriter {
 // TraceReader
 // =============================================================================
 
+/// Options for opening a trace file.
+#[derive(Debug, Clone, Default)]
+pub struct TraceReadOptions {
+    /// Dictionary for traces whose zstd frames were compressed with one.
+    #[cfg(feature = "trace-compression")]
+    pub dictionary: Option<TraceDictionary>,
+
+    /// Skip frames that fail to decode instead of failing the read.
+    ///
+    /// Only zstd-framed traces can isolate damage to a single frame; other
+    /// formats ignore this option. Skipped frames are reported by
+    /// `TraceReader::frame_losses`.
+    pub tolerant: bool,
+}
+
+impl TraceReadOptions {
+    /// Creates options with no dictionary in strict mode.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the dictionary used to decompress zstd frames.
+    #[cfg(feature = "trace-compression")]
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: TraceDictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Enables or disables tolerant mode.
+    #[must_use]
+    pub const fn tolerant(mut self, tolerant: bool) -> Self {
+        self.tolerant = tolerant;
+        self
+    }
+}
+
 /// Reader for loading trace files.
 ///
 /// Supports streaming reads where events are loaded incrementally.
@@ -863,6 +1108,9 @@ pub struct TraceReader {
     /// Position in decompressed buffer.
     #[cfg(feature = "trace-compression")]
     buffer_pos: usize,
+    /// Frame decoder for zstd-framed traces.
+    #[cfg(feature = "trace-compression")]
+    frames: Option<FrameDecoder>,
 }
 
 impl TraceReader {
@@ -877,8 +1125,25 @@ impl TraceReader {
     /// - The file has invalid magic bytes
     /// - The file version is unsupported
     /// - The file is compressed but the `trace-compression` feature is not enabled
+    /// - The file was compressed with a zstd dictionary (use
+    ///   [`open_with_options`](Self::open_with_options))
     /// - The metadata is corrupt
     pub fn open(path: impl AsRef<Path>) -> TraceFileResult<Self> {
+        Self::open_with_options(path, &TraceReadOptions::default())
+    }
+
+    /// Opens a trace file for reading with explicit options.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`open`](Self::open), plus
+    /// [`TraceFileError::DictionaryMismatch`] if the supplied dictionary is
+    /// not the one the trace was compressed with.
+    #[cfg_attr(not(feature = "trace-compression"), allow(unused_variables))]
+    pub fn open_with_options(
+        path: impl AsRef<Path>,
+        options: &TraceReadOptions,
+    ) -> TraceFileResult<Self> {
         let file = File::open(path)?;
         let mut reader = BufReader::new(file);
 
@@ -911,8 +1176,12 @@ impl TraceReader {
             let mut comp_byte = [0u8; 1];
             reader.read_exact(&mut comp_byte)?;
             match CompressionMode::from_byte(comp_byte[0]) {
+                // Frames and their header extension only exist from version 3.
+                Some(mode) if mode.is_framed() && version < 3 => {
+                    return Err(TraceFileError::UnsupportedCompression(comp_byte[0]));
+                }
                 Some(mode) => mode,
-                None if comp_byte[0] == 1 && is_compressed => {
+                None if matches!(comp_byte[0], 1 | 2) && is_compressed => {
                     return Err(TraceFileError::CompressionNotAvailable);
                 }
                 None if is_compressed => {
@@ -934,6 +1203,28 @@ impl TraceReader {
             return Err(TraceFileError::CompressionNotAvailable);
         }
 
+        // Read the dictionary descriptor of zstd-framed traces
+        let mut dictionary = None;
+        if compression.is_framed() {
+            let mut descriptor = [0u8; FRAMED_HEADER_EXT_SIZE];
+            reader.read_exact(&mut descriptor)?;
+            if flags & FLAG_DICTIONARY != 0 {
+                let id = u32::from_le_bytes([
+                    descriptor[0],
+                    descriptor[1],
+                    descriptor[2],
+                    descriptor[3],
+                ]);
+                let checksum = u32::from_le_bytes([
+                    descriptor[4],
+                    descriptor[5],
+                    descriptor[6],
+                    descriptor[7],
+                ]);
+                dictionary = Some((id, checksum));
+            }
+        }
+
         // Read metadata length
         let mut meta_len_bytes = [0u8; 4];
         reader.read_exact(&mut meta_len_bytes)?;
@@ -967,13 +1258,44 @@ impl TraceReader {
         let event_count = u64::from_le_bytes(event_count_bytes);
 
         // Calculate events start position (header size depends on version)
-        let header_size = if version >= 2 {
+        let mut header_size = if version >= 2 {
             HEADER_SIZE
         } else {
             HEADER_SIZE - 1
         };
+        if compression.is_framed() {
+            header_size += FRAMED_HEADER_EXT_SIZE;
+        }
         let events_start_pos = header_size as u64 + meta_len as u64 + 8;
 
+        #[cfg(feature = "trace-compression")]
+        let frames = if compression.is_framed() {
+            let dictionary = match (dictionary, options.dictionary.as_ref()) {
+                (None, _) => None,
+                (Some((id, checksum)), None) => {
+                    return Err(TraceFileError::DictionaryRequired { id, checksum });
+                }
+                (Some((id, checksum)), Some(supplied)) => {
+                    if supplied.checksum() != checksum {
+                        return Err(TraceFileError::DictionaryMismatch {
+                            expected_id: id,
+                            expected_checksum: checksum,
+                            found_id: supplied.id(),
+                            found_checksum: supplied.checksum(),
+                        });
+                    }
+                    Some(supplied)
+                }
+            };
+            let index = read_frame_index(&mut reader, events_start_pos)?;
+            reader.seek(SeekFrom::Start(events_start_pos))?;
+            Some(FrameDecoder::new(dictionary, index, options.tolerant)?)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "trace-compression"))]
+        let _ = dictionary;
+
         Ok(Self {
             reader,
             metadata,
@@ -985,6 +1307,8 @@ impl TraceReader {
             decompressed_buffer: Vec::new(),
             #[cfg(feature = "trace-compression")]
             buffer_pos: 0,
+            #[cfg(feature = "trace-compression")]
+            frames,
         })
     }
 
@@ -1018,20 +1342,61 @@ impl TraceReader {
         self.events_read
     }
 
+    /// Returns the frame index of a zstd-framed trace.
+    ///
+    /// Returns `None` for other formats and for framed traces whose writer
+    /// did not finish.
+    #[cfg(feature = "trace-compression")]
+    #[must_use]
+    pub fn frame_index(&self) -> Option<&[FrameIndexEntry]> {
+        self.frames.as_ref().and_then(FrameDecoder::index)
+    }
+
+    /// Returns the frames skipped so far in tolerant mode.
+    #[cfg(feature = "trace-compression")]
+    #[must_use]
+    pub fn frame_losses(&self) -> &[FrameLoss] {
+        self.frames.as_ref().map_or(&[][..], FrameDecoder::losses)
+    }
+
+    /// Positions the reader at the start of a zstd frame.
+    ///
+    /// The next [`read_event`](Self::read_event) returns the first event
+    /// stored in `frame`, and [`events_read`](Self::events_read) reports that
+    /// event's index. Seeking uses the footer index, so it costs one seek no
+    /// matter how far into the trace the frame lies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TraceFileError::FrameIndexMissing`] if the trace has no frame
+    /// index, or [`TraceFileError::FrameOutOfRange`] if `frame` is past the
+    /// last frame.
+    #[cfg(feature = "trace-compression")]
+    pub fn seek_to_frame(&mut self, frame: u64) -> TraceFileResult<()> {
+        let Some(frames) = self.frames.as_mut() else {
+            return Err(TraceFileError::FrameIndexMissing);
+        };
+        self.events_read = frames.seek_to_frame(&mut self.reader, frame)?;
+        Ok(())
+    }
+
     /// Returns an iterator over the events in the trace.
     ///
-    /// Events are read incrementally from the file.
+    /// Events are read incrementally from the file, starting after any
+    /// events already consumed through [`read_event`](Self::read_event).
     /// Automatically handles decompression for compressed files.
     #[must_use]
     pub fn events(self) -> TraceEventIterator {
         TraceEventIterator {
             reader: self.reader,
-            remaining: self.event_count,
+            remaining: self.event_count.saturating_sub(self.events_read),
             compression: self.compression,
             #[cfg(feature = "trace-compression")]
             decompressed_buffer: self.decompressed_buffer,
             #[cfg(feature = "trace-compression")]
             buffer_pos: self.buffer_pos,
+            #[cfg(feature = "trace-compression")]
+            frames: self.frames,
         }
     }
 
@@ -1048,6 +1413,14 @@ impl TraceReader {
             return Ok(None);
         }
 
+        #[cfg(feature = "trace-compression")]
+        if let Some(frames) = self.frames.as_mut() {
+            let mut remaining = self.event_count - self.events_read;
+            let event = frames.next_event(&mut self.reader, &mut remaining);
+            self.events_read = self.event_count - remaining;
+            return event;
+        }
+
         #[cfg(feature = "trace-compression")]
         if self.compression.is_compressed() {
             return self.read_compressed_event();
@@ -1177,6 +1550,9 @@ impl TraceReader {
         {
             self.decompressed_buffer.clear();
             self.buffer_pos = 0;
+            if let Some(frames) = self.frames.as_mut() {
+                frames.reset();
+            }
         }
 
         Ok(())
@@ -1221,6 +1597,9 @@ pub struct TraceEventIterator {
     /// Position in decompressed buffer.
     #[cfg(feature = "trace-compression")]
     buffer_pos: usize,
+    /// Frame decoder for zstd-framed traces.
+    #[cfg(feature = "trace-compression")]
+    frames: Option<FrameDecoder>,
 }
 
 impl Iterator for TraceEventIterator {
@@ -1231,6 +1610,13 @@ impl Iterator for TraceEventIterator {
             return None;
         }
 
+        #[cfg(feature = "trace-compression")]
+        if let Some(frames) = self.frames.as_mut() {
+            return frames
+                .next_event(&mut self.reader, &mut self.remaining)
+                .transpose();
+        }
+
         #[cfg(feature = "trace-compression")]
         if self.compression.is_compressed() {
             return Some(self.next_compressed());
@@ -1246,6 +1632,13 @@ impl Iterator for TraceEventIterator {
 }
 
 impl TraceEventIterator {
+    /// Returns the frames skipped so far in tolerant mode.
+    #[cfg(feature = "trace-compression")]
+    #[must_use]
+    pub fn frame_losses(&self) -> &[FrameLoss] {
+        self.frames.as_ref().map_or(&[][..], FrameDecoder::losses)
+    }
+
     /// Reads the next uncompressed event.
     fn next_uncompressed(&mut self) -> TraceFileResult<ReplayEvent> {
         // Read event length
@@ -2412,4 +2805,298 @@ mod tests {
             );
         }
     }
+
+    // =========================================================================
+    // Framed zstd Tests (feature-gated)
+    // =========================================================================
+
+    #[cfg(feature = "trace-compression")]
+    mod zstd_tests {
+        use super::*;
+        use crate::trace::framed::{
+            FRAME_HEADER_SIZE, FRAME_INDEX_ENTRY_SIZE, FRAME_INDEX_TRAILER_SIZE,
+        };
+
+        /// Small, repetitive events typical of long soak runs.
+        fn soak_events(count: u64) -> Vec<ReplayEvent> {
+            (0..count)
+                .map(|i| match i % 5 {
+                    0 => ReplayEvent::TaskScheduled {
+                        task: CompactTaskId(((i % 24) << 32) | 1),
+                        at_tick: i,
+                    },
+                    1 => ReplayEvent::TaskYielded {
+                        task: CompactTaskId((((i * 7) % 24) << 32) | 1),
+                    },
+                    2 => ReplayEvent::TimerCreated {
+                        timer_id: i,
+                        deadline_nanos: i * 1_000_000 + (i % 13) * 250_000,
+                    },
+                    3 => ReplayEvent::IoResult {
+                        token: 0x100 + i % 16,
+                        bytes: ((i * 37) % 4096) as i64,
+                    },
+                    _ => ReplayEvent::TimeAdvanced {
+                        from_nanos: i * 1_000_000,
+                        to_nanos: (i + 1) * 1_000_000,
+                    },
+                })
+                .collect()
+        }
+
+        fn zstd_config(frame_size: usize) -> TraceFileConfig {
+            TraceFileConfig::new()
+                .with_compression(CompressionMode::Zstd {
+                    level: DEFAULT_ZSTD_LEVEL,
+                })
+                .with_chunk_size(frame_size)
+        }
+
+        fn write_events(path: &std::path::Path, config: TraceFileConfig, events: &[ReplayEvent]) {
+            write_trace_with_config(path, &TraceMetadata::new(7), events, config)
+                .expect("write trace");
+        }
+
+        fn file_version(path: &std::path::Path) -> u16 {
+            let bytes = std::fs::read(path).expect("read trace bytes");
+            u16::from_le_bytes([bytes[TRACE_MAGIC.len()], bytes[TRACE_MAGIC.len() + 1]])
+        }
+
+        #[test]
+        fn round_trip_across_all_formats() {
+            let events = soak_events(2_000);
+            let dictionary = TraceDictionary::train(&events, 2048).expect("train dictionary");
+            let cases = [
+                ("uncompressed", TraceFileConfig::new(), None, 2),
+                (
+                    "lz4",
+                    TraceFileConfig::new().with_compression(CompressionMode::Lz4 { level: 1 }),
+                    None,
+                    2,
+                ),
+                ("zstd", zstd_config(4096), None, 3),
+                (
+                    "zstd+dict",
+                    zstd_config(4096).with_dictionary(dictionary.clone()),
+                    Some(dictionary),
+                    3,
+                ),
+            ];
+
+            for (label, config, dictionary, version) in cases {
+                let temp = NamedTempFile::new().expect("create temp file");
+                let framed = config.compression.is_framed();
+                write_events(temp.path(), config, &events);
+                assert_eq!(file_version(temp.path()), version, "{label}: version");
+
+                let mut options = TraceReadOptions::new();
+                if let Some(dictionary) = dictionary {
+                    options = options.with_dictionary(dictionary);
+                }
+                let reader =
+                    TraceReader::open_with_options(temp.path(), &options).expect("open reader");
+                assert_eq!(reader.event_count(), events.len() as u64, "{label}");
+                assert_eq!(reader.frame_index().is_some(), framed, "{label}: index");
+                assert_eq!(reader.load_all().expect("load all"), events, "{label}");
+
+                let iterated = TraceReader::open_with_options(temp.path(), &options)
+                    .expect("open reader")
+                    .events()
+                    .collect::<TraceFileResult<Vec<_>>>()
+                    .expect("iterate events");
+                assert_eq!(iterated, events, "{label}: iterator");
+            }
+        }
+
+        #[test]
+        fn seek_to_frame_resumes_at_frame_boundary() {
+            let temp = NamedTempFile::new().expect("create temp file");
+            let events = soak_events(2_000);
+            write_events(temp.path(), zstd_config(512), &events);
+
+            let mut reader = TraceReader::open(temp.path()).expect("open reader");
+            let index = reader.frame_index().expect("frame index").to_vec();
+            assert!(index.len() > 8, "expected many frames, got {}", index.len());
+            let indexed: u64 = index.iter().map(|entry| u64::from(entry.event_count)).sum();
+            assert_eq!(indexed, events.len() as u64);
+
+            // Seek backwards and forwards, including the last frame.
+            for frame in [3, 0, index.len() - 1, 5] {
+                let entry = index[frame];
+                reader.seek_to_frame(frame as u64).expect("seek to frame");
+                assert_eq!(reader.events_read(), entry.first_event);
+                let first = entry.first_event as usize;
+                for expected in &events[first..first + entry.event_count as usize] {
+                    let event = reader.read_event().expect("read event");
+                    assert_eq!(event.as_ref(), Some(expected), "frame {frame}");
+                }
+            }
+
+            // The iterator picks up where the reader left off.
+            reader.seek_to_frame(7).expect("seek to frame");
+            let tail = reader
+                .events()
+                .collect::<TraceFileResult<Vec<_>>>()
+                .expect("iterate tail");
+            assert_eq!(tail, events[index[7].first_event as usize..]);
+
+            let mut reader = TraceReader::open(temp.path()).expect("open reader");
+            let err = reader
+                .seek_to_frame(index.len() as u64)
+                .expect_err("seek past last frame");
+            assert!(
+                matches!(err, TraceFileError::FrameOutOfRange { frame, frames }
+                    if frame == index.len() as u64 && frames == index.len() as u64),
+                "got: {err:?}"
+            );
+        }
+
+        #[test]
+        fn seek_requires_frame_index() {
+            let events = soak_events(500);
+
+            let plain = NamedTempFile::new().expect("create temp file");
+            write_events(plain.path(), TraceFileConfig::new(), &events);
+            let mut reader = TraceReader::open(plain.path()).expect("open reader");
+            assert!(matches!(
+                reader.seek_to_frame(0),
+                Err(TraceFileError::FrameIndexMissing)
+            ));
+
+            // Dropping the footer (as after a crash) keeps sequential reads working.
+            let framed = NamedTempFile::new().expect("create temp file");
+            write_events(framed.path(), zstd_config(512), &events);
+            let index_len = TraceReader::open(framed.path())
+                .expect("open reader")
+                .frame_index()
+                .expect("frame index")
+                .len();
+            let bytes = std::fs::read(framed.path()).expect("read trace bytes");
+            let footer_len = index_len * FRAME_INDEX_ENTRY_SIZE + FRAME_INDEX_TRAILER_SIZE;
+            std::fs::write(framed.path(), &bytes[..bytes.len() - footer_len])
+                .expect("truncate footer");
+
+            let mut reader = TraceReader::open(framed.path()).expect("open reader");
+            assert!(reader.frame_index().is_none());
+            assert!(matches!(
+                reader.seek_to_frame(0),
+                Err(TraceFileError::FrameIndexMissing)
+            ));
+            assert_eq!(reader.load_all().expect("load all"), events);
+        }
+
+        #[test]
+        fn dictionary_mismatch_is_detected_by_checksum() {
+            let temp = NamedTempFile::new().expect("create temp file");
+            let events = soak_events(2_000);
+            let dictionary = TraceDictionary::train(&events, 2048).expect("train dictionary");
+            write_events(
+                temp.path(),
+                zstd_config(1024).with_dictionary(dictionary.clone()),
+                &events,
+            );
+
+            let err = TraceReader::open(temp.path()).expect_err("dictionary is required");
+            assert!(
+                matches!(err, TraceFileError::DictionaryRequired { id, checksum }
+                    if id == dictionary.id() && checksum == dictionary.checksum()),
+                "got: {err:?}"
+            );
+
+            let other = TraceDictionary::from_bytes(vec![0x5a; 1024]);
+            let options = TraceReadOptions::new().with_dictionary(other.clone());
+            let err = TraceReader::open_with_options(temp.path(), &options)
+                .expect_err("wrong dictionary must be rejected");
+            assert!(
+                matches!(
+                    err,
+                    TraceFileError::DictionaryMismatch {
+                        expected_checksum,
+                        found_checksum,
+                        ..
+                    } if expected_checksum == dictionary.checksum()
+                        && found_checksum == other.checksum()
+                ),
+                "got: {err:?}"
+            );
+            let message = err.to_string();
+            assert!(message.contains("dictionary mismatch"), "{message}");
+            assert!(
+                message.contains(&format!("{:#010x}", dictionary.checksum())),
+                "{message}"
+            );
+
+            let options = TraceReadOptions::new().with_dictionary(dictionary);
+            let reader =
+                TraceReader::open_with_options(temp.path(), &options).expect("open reader");
+            assert_eq!(reader.load_all().expect("load all"), events);
+        }
+
+        #[test]
+        fn tolerant_reader_isolates_corrupt_frame() {
+            let temp = NamedTempFile::new().expect("create temp file");
+            let events = soak_events(2_000);
+            write_events(temp.path(), zstd_config(512), &events);
+
+            let index = TraceReader::open(temp.path())
+                .expect("open reader")
+                .frame_index()
+                .expect("frame index")
+                .to_vec();
+            let damaged = index[2];
+            let payload_start = damaged.offset as usize + FRAME_HEADER_SIZE;
+            let payload_len = (index[3].offset - damaged.offset) as usize - FRAME_HEADER_SIZE;
+            let mut bytes = std::fs::read(temp.path()).expect("read trace bytes");
+            bytes[payload_start + payload_len / 2] ^= 0xff;
+            std::fs::write(temp.path(), &bytes).expect("write corrupted trace");
+
+            // Strict mode surfaces the damage.
+            let err = TraceReader::open(temp.path())
+                .expect("open reader")
+                .load_all()
+                .expect_err("corrupt frame must fail strict reads");
+            assert!(
+                matches!(
+                    err,
+                    TraceFileError::Decompression(_)
+                        | TraceFileError::FrameChecksumMismatch { frame: 2, .. }
+                ),
+                "got: {err:?}"
+            );
+
+            // Tolerant mode loses exactly the damaged frame.
+            let first = damaged.first_event as usize;
+            let lost = first..first + damaged.event_count as usize;
+            let expected: Vec<_> = events
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !lost.contains(i))
+                .map(|(_, event)| event.clone())
+                .collect();
+            let options = TraceReadOptions::new().tolerant(true);
+
+            let mut reader =
+                TraceReader::open_with_options(temp.path(), &options).expect("open reader");
+            let mut recovered = Vec::new();
+            while let Some(event) = reader.read_event().expect("tolerant read") {
+                recovered.push(event);
+            }
+            assert_eq!(recovered, expected);
+            assert_eq!(reader.frame_losses().len(), 1);
+            let loss = &reader.frame_losses()[0];
+            assert_eq!(loss.frame, 2);
+            assert_eq!(loss.first_event, Some(damaged.first_event));
+            assert_eq!(loss.event_count, damaged.event_count);
+
+            let mut iter = TraceReader::open_with_options(temp.path(), &options)
+                .expect("open reader")
+                .events();
+            let iterated = iter
+                .by_ref()
+                .collect::<TraceFileResult<Vec<_>>>()
+                .expect("tolerant iteration");
+            assert_eq!(iterated, expected);
+            assert_eq!(iter.frame_losses().len(), 1);
+        }
+    }
 }
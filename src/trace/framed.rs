@@ -0,0 +1,605 @@
+//! Framed zstd codec for trace files.
+//!
+//! Zstd-compressed traces batch length-prefixed events into frames of roughly
+//! [`TraceFileConfig::chunk_size`](super::file::TraceFileConfig::chunk_size)
+//! bytes and compress each frame independently, so decoding can start at any
+//! frame boundary and a damaged frame does not take its neighbours with it.
+//! Every frame carries a CRC32 of its decompressed payload, and a footer index
+//! of frame offsets lets readers seek without scanning the file.
+//!
+//! Traces made of many tiny events compress poorly one frame at a time because
+//! each frame starts with an empty history. An optional [`TraceDictionary`],
+//! trained offline from representative traces (`frankenlab trace train-dict`),
+//! primes both the compressor and the decompressor with that shared history.
+//! The dictionary itself is not stored in the trace; the header records its id
+//! and checksum so a reader holding the wrong dictionary fails up front.
+//!
+//! # Layout
+//!
+//! ```text
+//! Frame:   payload_len u32 | raw_len u32 | event_count u32 | crc32(raw) u32 | zstd payload
+//! Footer:  [offset u64 | first_event u64 | event_count u32] * frame_count
+//!          | frame_count u64 | index_offset u64 | "ASUPRIDX"
+//! ```
+//!
+//! All integers are little-endian. The footer is only written by
+//! [`TraceWriter::finish`](super::file::TraceWriter::finish) (or on drop), so a
+//! trace from a crashed writer still decodes sequentially; it just cannot seek.
+
+use super::file::{
+    MAX_COMPRESSED_CHUNK_LEN, TraceFileError, TraceFileResult, truncated_or_io, validate_event_len,
+};
+use super::replay::ReplayEvent;
+use crate::tracing_compat::warn;
+use std::fmt;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// Default zstd compression level for framed traces.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Default maximum size of a trained dictionary (16 KiB).
+pub const DEFAULT_DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// Size of the header preceding every frame payload.
+pub const FRAME_HEADER_SIZE: usize = 4 + 4 + 4 + 4;
+
+/// Size of one frame index entry in the footer.
+pub const FRAME_INDEX_ENTRY_SIZE: usize = 8 + 8 + 4;
+
+/// Size of the fixed trailer closing the footer.
+pub const FRAME_INDEX_TRAILER_SIZE: usize = 8 + 8 + 8;
+
+/// Magic bytes closing the frame index footer.
+pub const FRAME_INDEX_MAGIC: &[u8; 8] = b"ASUPRIDX";
+
+/// Magic number opening a zstd-format (trained) dictionary.
+const ZSTD_DICT_MAGIC: [u8; 4] = 0xEC30_A437_u32.to_le_bytes();
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(buf)
+}
+
+fn le_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+fn frame_field_len(field: &'static str, len: usize) -> TraceFileResult<u32> {
+    if len > MAX_COMPRESSED_CHUNK_LEN {
+        return Err(TraceFileError::OversizedField {
+            field,
+            actual: len as u64,
+            max: MAX_COMPRESSED_CHUNK_LEN as u64,
+        });
+    }
+    // MAX_COMPRESSED_CHUNK_LEN fits in u32, so this cannot truncate.
+    Ok(len as u32)
+}
+
+/// Encodes one event exactly as it appears inside a frame: a `u32` length
+/// prefix followed by the msgpack body.
+fn encode_framed_event(event: &ReplayEvent) -> TraceFileResult<Vec<u8>> {
+    let body = rmp_serde::to_vec(event)?;
+    let len = u32::try_from(body.len()).map_err(|_| TraceFileError::OversizedField {
+        field: "event_len",
+        actual: body.len() as u64,
+        max: u64::from(u32::MAX),
+    })?;
+    let mut encoded = Vec::with_capacity(4 + body.len());
+    encoded.extend_from_slice(&len.to_le_bytes());
+    encoded.extend_from_slice(&body);
+    Ok(encoded)
+}
+
+// =============================================================================
+// Dictionary
+// =============================================================================
+
+/// A zstd dictionary shared by the writer and readers of framed traces.
+///
+/// The trace header records the dictionary's [`id`](Self::id) and CRC32
+/// [`checksum`](Self::checksum). Opening a trace with a different dictionary
+/// fails with [`TraceFileError::DictionaryMismatch`] before any frame is
+/// decoded, rather than surfacing as garbled events later.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TraceDictionary {
+    bytes: Arc<[u8]>,
+    id: u32,
+    checksum: u32,
+}
+
+impl TraceDictionary {
+    /// Wraps dictionary bytes, such as a file written by
+    /// `frankenlab trace train-dict`.
+    #[must_use]
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        let bytes: Vec<u8> = bytes.into();
+        let id = if bytes.len() >= 8 && bytes[..4] == ZSTD_DICT_MAGIC {
+            le_u32(&bytes[4..8])
+        } else {
+            0
+        };
+        let checksum = crc32fast::hash(&bytes);
+        Self {
+            bytes: bytes.into(),
+            id,
+            checksum,
+        }
+    }
+
+    /// Trains a dictionary of at most `max_size` bytes from sample events.
+    ///
+    /// Each sample is encoded exactly as it is stored inside a frame, so the
+    /// dictionary learns the length prefixes and msgpack field layout that
+    /// dominate small-event traces.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an event cannot be serialized, or
+    /// [`TraceFileError::Compression`] if zstd cannot train on the samples
+    /// (typically because there are too few of them).
+    pub fn train<'a>(
+        events: impl IntoIterator<Item = &'a ReplayEvent>,
+        max_size: usize,
+    ) -> TraceFileResult<Self> {
+        let samples = events
+            .into_iter()
+            .map(encode_framed_event)
+            .collect::<TraceFileResult<Vec<_>>>()?;
+        let bytes = zstd::dict::from_samples(&samples, max_size).map_err(|e| {
+            TraceFileError::Compression(format!(
+                "dictionary training on {} samples failed: {e}",
+                samples.len()
+            ))
+        })?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Returns the dictionary id embedded by zstd training, or 0 for a raw
+    /// content dictionary.
+    #[must_use]
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the CRC32 of the dictionary bytes.
+    #[must_use]
+    pub const fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Returns the raw dictionary bytes.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Debug for TraceDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceDictionary")
+            .field("id", &format_args!("{:#010x}", self.id))
+            .field("checksum", &format_args!("{:#010x}", self.checksum))
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+// =============================================================================
+// Frame Index
+// =============================================================================
+
+/// Location of one compressed frame, as recorded in the footer index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameIndexEntry {
+    /// Byte offset of the frame header from the start of the file.
+    pub offset: u64,
+    /// Index of the first event stored in the frame.
+    pub first_event: u64,
+    /// Number of events stored in the frame.
+    pub event_count: u32,
+}
+
+/// A frame skipped by a tolerant reader because it failed to decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameLoss {
+    /// Zero-based frame number.
+    pub frame: u64,
+    /// Index of the first lost event, known when the trace has a frame index.
+    pub first_event: Option<u64>,
+    /// Number of events lost with the frame.
+    pub event_count: u32,
+    /// Why the frame was rejected.
+    pub reason: String,
+}
+
+/// Reads the footer frame index, returning `None` when the trace has no
+/// well-formed footer (for example, because its writer never finished).
+pub(crate) fn read_frame_index<R: Read + Seek>(
+    reader: &mut R,
+    events_start: u64,
+) -> TraceFileResult<Option<Vec<FrameIndexEntry>>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let trailer_len = FRAME_INDEX_TRAILER_SIZE as u64;
+    let Some(trailer_start) = file_len.checked_sub(trailer_len) else {
+        return Ok(None);
+    };
+    if trailer_start < events_start {
+        return Ok(None);
+    }
+
+    let mut trailer = [0u8; FRAME_INDEX_TRAILER_SIZE];
+    reader.seek(SeekFrom::Start(trailer_start))?;
+    reader.read_exact(&mut trailer)?;
+    if &trailer[16..] != FRAME_INDEX_MAGIC {
+        return Ok(None);
+    }
+    let frame_count = le_u64(&trailer[0..8]);
+    let index_offset = le_u64(&trailer[8..16]);
+    let index_end = frame_count
+        .checked_mul(FRAME_INDEX_ENTRY_SIZE as u64)
+        .and_then(|len| index_offset.checked_add(len));
+    if index_offset < events_start || index_end != Some(trailer_start) {
+        return Ok(None);
+    }
+
+    // The length check above bounds the index by the file size.
+    let mut raw = vec![0u8; (trailer_start - index_offset) as usize];
+    reader.seek(SeekFrom::Start(index_offset))?;
+    reader.read_exact(&mut raw).map_err(truncated_or_io)?;
+
+    let mut index = Vec::with_capacity(raw.len() / FRAME_INDEX_ENTRY_SIZE);
+    let mut next_offset = events_start;
+    let mut next_event = 0u64;
+    for chunk in raw.chunks_exact(FRAME_INDEX_ENTRY_SIZE) {
+        let entry = FrameIndexEntry {
+            offset: le_u64(&chunk[0..8]),
+            first_event: le_u64(&chunk[8..16]),
+            event_count: le_u32(&chunk[16..20]),
+        };
+        if entry.offset < next_offset
+            || entry.offset >= index_offset
+            || entry.first_event != next_event
+        {
+            return Ok(None);
+        }
+        next_offset = entry.offset + FRAME_HEADER_SIZE as u64;
+        next_event = entry.first_event + u64::from(entry.event_count);
+        index.push(entry);
+    }
+    Ok(Some(index))
+}
+
+// =============================================================================
+// Encoder
+// =============================================================================
+
+/// Compresses event batches into frames and records the frame index.
+pub(crate) struct FrameEncoder {
+    compressor: zstd::bulk::Compressor<'static>,
+    index: Vec<FrameIndexEntry>,
+    next_event: u64,
+}
+
+impl FrameEncoder {
+    pub(crate) fn new(level: i32, dictionary: Option<&TraceDictionary>) -> TraceFileResult<Self> {
+        let compressor = match dictionary {
+            Some(dictionary) => {
+                zstd::bulk::Compressor::with_dictionary(level, dictionary.as_bytes())
+            }
+            None => zstd::bulk::Compressor::new(level),
+        }
+        .map_err(|e| TraceFileError::Compression(e.to_string()))?;
+        Ok(Self {
+            compressor,
+            index: Vec::new(),
+            next_event: 0,
+        })
+    }
+
+    /// Compresses `raw` (length-prefixed events) into a frame that will be
+    /// written at file offset `offset`.
+    pub(crate) fn encode(
+        &mut self,
+        raw: &[u8],
+        event_count: u32,
+        offset: u64,
+    ) -> TraceFileResult<Vec<u8>> {
+        let payload = self
+            .compressor
+            .compress(raw)
+            .map_err(|e| TraceFileError::Compression(e.to_string()))?;
+        let payload_len = frame_field_len("compressed_frame_len", payload.len())?;
+        let raw_len = frame_field_len("decompressed_frame_len", raw.len())?;
+
+        let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+        frame.extend_from_slice(&payload_len.to_le_bytes());
+        frame.extend_from_slice(&raw_len.to_le_bytes());
+        frame.extend_from_slice(&event_count.to_le_bytes());
+        frame.extend_from_slice(&crc32fast::hash(raw).to_le_bytes());
+        frame.extend_from_slice(&payload);
+
+        self.index.push(FrameIndexEntry {
+            offset,
+            first_event: self.next_event,
+            event_count,
+        });
+        self.next_event += u64::from(event_count);
+        Ok(frame)
+    }
+
+    /// Serializes the footer for an index written at file offset `index_offset`.
+    pub(crate) fn encode_footer(&self, index_offset: u64) -> Vec<u8> {
+        let mut footer = Vec::with_capacity(
+            self.index.len() * FRAME_INDEX_ENTRY_SIZE + FRAME_INDEX_TRAILER_SIZE,
+        );
+        for entry in &self.index {
+            footer.extend_from_slice(&entry.offset.to_le_bytes());
+            footer.extend_from_slice(&entry.first_event.to_le_bytes());
+            footer.extend_from_slice(&entry.event_count.to_le_bytes());
+        }
+        footer.extend_from_slice(&(self.index.len() as u64).to_le_bytes());
+        footer.extend_from_slice(&index_offset.to_le_bytes());
+        footer.extend_from_slice(FRAME_INDEX_MAGIC);
+        footer
+    }
+}
+
+// =============================================================================
+// Decoder
+// =============================================================================
+
+enum FrameRead {
+    Loaded,
+    Corrupt {
+        frame: u64,
+        error: TraceFileError,
+        header_events: u32,
+        payload_consumed: bool,
+    },
+}
+
+/// Decodes frames one at a time, holding at most one decompressed frame.
+pub(crate) struct FrameDecoder {
+    decompressor: zstd::bulk::Decompressor<'static>,
+    index: Option<Vec<FrameIndexEntry>>,
+    tolerant: bool,
+    losses: Vec<FrameLoss>,
+    buffer: Vec<u8>,
+    buffer_pos: usize,
+    next_frame: u64,
+    /// Set after skipping a frame: the next frame is located via the index
+    /// instead of trusting the damaged frame's length prefix.
+    resync: bool,
+}
+
+impl FrameDecoder {
+    pub(crate) fn new(
+        dictionary: Option<&TraceDictionary>,
+        index: Option<Vec<FrameIndexEntry>>,
+        tolerant: bool,
+    ) -> TraceFileResult<Self> {
+        let decompressor = match dictionary {
+            Some(dictionary) => zstd::bulk::Decompressor::with_dictionary(dictionary.as_bytes()),
+            None => zstd::bulk::Decompressor::new(),
+        }
+        .map_err(|e| TraceFileError::Decompression(e.to_string()))?;
+        Ok(Self {
+            decompressor,
+            index,
+            tolerant,
+            losses: Vec::new(),
+            buffer: Vec::new(),
+            buffer_pos: 0,
+            next_frame: 0,
+            resync: false,
+        })
+    }
+
+    pub(crate) fn index(&self) -> Option<&[FrameIndexEntry]> {
+        self.index.as_deref()
+    }
+
+    pub(crate) fn losses(&self) -> &[FrameLoss] {
+        &self.losses
+    }
+
+    fn entry(&self, frame: u64) -> Option<FrameIndexEntry> {
+        let index = self.index.as_ref()?;
+        index.get(usize::try_from(frame).ok()?).copied()
+    }
+
+    /// Forgets decoding progress; the caller repositions the reader at the
+    /// first frame.
+    pub(crate) fn reset(&mut self) {
+        self.buffer.clear();
+        self.buffer_pos = 0;
+        self.next_frame = 0;
+        self.resync = false;
+        self.losses.clear();
+    }
+
+    /// Positions `reader` at the start of `frame` and returns the index of
+    /// the first event stored in it.
+    pub(crate) fn seek_to_frame<R: Seek>(
+        &mut self,
+        reader: &mut R,
+        frame: u64,
+    ) -> TraceFileResult<u64> {
+        let Some(index) = self.index.as_deref() else {
+            return Err(TraceFileError::FrameIndexMissing);
+        };
+        let entry = self.entry(frame).ok_or(TraceFileError::FrameOutOfRange {
+            frame,
+            frames: index.len() as u64,
+        })?;
+        reader.seek(SeekFrom::Start(entry.offset))?;
+        self.buffer.clear();
+        self.buffer_pos = 0;
+        self.next_frame = frame;
+        self.resync = false;
+        Ok(entry.first_event)
+    }
+
+    /// Decodes the next event, loading frames as needed.
+    ///
+    /// `remaining` counts events still expected from the trace; it is
+    /// decremented for every event returned and for every event lost with a
+    /// skipped frame.
+    pub(crate) fn next_event<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        remaining: &mut u64,
+    ) -> TraceFileResult<Option<ReplayEvent>> {
+        while self.buffer_pos >= self.buffer.len() {
+            if *remaining == 0 {
+                return Ok(None);
+            }
+            match self.load_frame(reader)? {
+                FrameRead::Loaded => {}
+                FrameRead::Corrupt {
+                    frame,
+                    error,
+                    header_events,
+                    payload_consumed,
+                } => {
+                    let lost =
+                        self.skip_corrupt_frame(frame, error, header_events, payload_consumed)?;
+                    *remaining = remaining.saturating_sub(u64::from(lost));
+                }
+            }
+        }
+        if *remaining == 0 {
+            return Ok(None);
+        }
+
+        let start = self.buffer_pos;
+        let len_bytes = self
+            .buffer
+            .get(start..start + 4)
+            .ok_or(TraceFileError::Truncated)?;
+        let len = le_u32(len_bytes) as usize;
+        validate_event_len(len)?;
+        let body = self
+            .buffer
+            .get(start + 4..start + 4 + len)
+            .ok_or(TraceFileError::Truncated)?;
+        let event: ReplayEvent = rmp_serde::from_slice(body)?;
+        self.buffer_pos = start + 4 + len;
+        *remaining -= 1;
+        Ok(Some(event))
+    }
+
+    fn load_frame<R: Read + Seek>(&mut self, reader: &mut R) -> TraceFileResult<FrameRead> {
+        let frame = self.next_frame;
+        if std::mem::take(&mut self.resync) {
+            if let Some(entry) = self.entry(frame) {
+                reader.seek(SeekFrom::Start(entry.offset))?;
+            }
+        }
+        self.next_frame += 1;
+        self.buffer.clear();
+        self.buffer_pos = 0;
+
+        let mut header = [0u8; FRAME_HEADER_SIZE];
+        reader.read_exact(&mut header).map_err(truncated_or_io)?;
+        let payload_len = le_u32(&header[0..4]) as usize;
+        let raw_len = le_u32(&header[4..8]) as usize;
+        let header_events = le_u32(&header[8..12]);
+        let expected = le_u32(&header[12..16]);
+
+        // Guard against decompression bombs and corrupt length prefixes.
+        for (field, len) in [
+            ("compressed_frame_len", payload_len),
+            ("decompressed_frame_len", raw_len),
+        ] {
+            if let Err(error) = frame_field_len(field, len) {
+                return Ok(FrameRead::Corrupt {
+                    frame,
+                    error,
+                    header_events,
+                    payload_consumed: false,
+                });
+            }
+        }
+
+        let mut payload = vec![0u8; payload_len];
+        reader.read_exact(&mut payload).map_err(truncated_or_io)?;
+
+        let raw = match self.decompressor.decompress(&payload, raw_len) {
+            Ok(raw) => raw,
+            Err(e) => {
+                return Ok(FrameRead::Corrupt {
+                    frame,
+                    error: TraceFileError::Decompression(format!("frame {frame}: {e}")),
+                    header_events,
+                    payload_consumed: true,
+                });
+            }
+        };
+        let actual = crc32fast::hash(&raw);
+        if raw.len() != raw_len || actual != expected {
+            return Ok(FrameRead::Corrupt {
+                frame,
+                error: TraceFileError::FrameChecksumMismatch {
+                    frame,
+                    expected,
+                    actual,
+                },
+                header_events,
+                payload_consumed: true,
+            });
+        }
+
+        self.buffer = raw;
+        Ok(FrameRead::Loaded)
+    }
+
+    /// Records a lost frame in tolerant mode, or returns `error` when the
+    /// loss cannot be isolated to this frame.
+    fn skip_corrupt_frame(
+        &mut self,
+        frame: u64,
+        error: TraceFileError,
+        header_events: u32,
+        payload_consumed: bool,
+    ) -> TraceFileResult<u32> {
+        let entry = self.entry(frame);
+        // Without the index, skipping is only safe if the frame's own length
+        // prefix was plausible enough to step over its payload.
+        if !self.tolerant || !(payload_consumed || entry.is_some()) {
+            return Err(error);
+        }
+
+        let event_count = entry.map_or(header_events, |entry| entry.event_count);
+        warn!(
+            frame,
+            event_count,
+            error = %error,
+            "skipping corrupt trace frame"
+        );
+        self.losses.push(FrameLoss {
+            frame,
+            first_event: entry.map(|entry| entry.first_event),
+            event_count,
+            reason: error.to_string(),
+        });
+        self.resync = self.index.is_some();
+        Ok(event_count)
+    }
+}
+
+impl fmt::Debug for FrameDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameDecoder")
+            .field("frames", &self.index.as_ref().map(Vec::len))
+            .field("tolerant", &self.tolerant)
+            .field("next_frame", &self.next_frame)
+            .field("losses", &self.losses.len())
+            .finish_non_exhaustive()
+    }
+}
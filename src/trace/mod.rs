@@ -24,10 +24,12 @@
 //! # Submodules
 //!
 //! - [`event`]: Observability trace events for debugging and analysis
+//! - [`analysis`]: Critical-path, flamegraph and semantic diff analysis of recorded traces
 //! - [`replay`]: Compact replay events for deterministic record/replay
 //! - [`recorder`]: Trace recorder for Lab runtime instrumentation
 //! - [`replayer`]: Trace replayer for deterministic replay with stepping support
 //! - [`file`](mod@file): Binary file format for trace persistence
+//! - `framed`: Framed zstd codec with dictionaries and a frame index (`trace-compression`)
 //! - [`buffer`]: Ring buffer for recent events
 //! - [`format`](mod@format): Output formatting utilities
 //! - [`streaming`]: Streaming replay for large traces with O(1) memory
@@ -39,7 +41,9 @@
 //! - [`geodesic`]: Low-switch-cost schedule normalization
 //! - [`dpor`]: DPOR race detection and backtracking
 //! - [`tla_export`]: TLA+ export for model checking
+//! - [`symbols`]: Spawn-site symbol maps and post-hoc trace annotation
 
+pub mod analysis;
 pub mod boundary;
 pub mod buffer;
 pub mod canonicalize;
@@ -59,6 +63,8 @@ pub mod event_structure;
 pub mod file;
 pub mod filter;
 pub mod format;
+#[cfg(feature = "trace-compression")]
+pub mod framed;
 pub mod geodesic;
 pub mod gf2;
 pub mod incident;
@@ -73,6 +79,7 @@ pub mod replay;
 pub mod replayer;
 pub mod scoring;
 pub mod streaming;
+pub mod symbols;
 pub mod tla_export;
 
 pub use boundary::{SquareComplex, matmul_gf2};
@@ -120,7 +127,7 @@ pub use event_structure::{
 };
 pub use file::{
     CompressionMode, TRACE_FILE_VERSION, TRACE_MAGIC, TraceEventIterator, TraceFileConfig,
-    TraceFileError, TraceReader, TraceWriter, read_trace, write_trace,
+    TraceFileError, TraceReadOptions, TraceReader, TraceWriter, read_trace, write_trace,
 };
 pub use filter::{EventCategory, FilterBuilder, FilterableEvent, TraceFilter};
 #[cfg(feature = "test-internals")]
@@ -190,6 +197,10 @@ pub use streaming::{
     StreamingReplayError, StreamingReplayResult, StreamingReplayer, TraceEvidenceChunk,
     TraceEvidenceSink, TraceEvidenceStreamConfig, TraceEvidenceStreamStats, TraceEvidenceStreamer,
 };
+pub use symbols::{
+    SYMBOL_MAP_SCHEMA_VERSION, SiteKind, SpawnSiteId, SymbolEntry, SymbolMap, SymbolMapBuilder,
+    SymbolMapError, TRACE_ANNOTATIONS_SCHEMA_VERSION, TaskAnnotation, TraceAnnotations, annotate,
+};
 pub use tla_export::{TlaExporter, TlaModule, TlaStateSnapshot};
 
 // ============================================================================
@@ -30,7 +30,7 @@
 //! - All operations are inline-friendly
 //! - Disabled recorders have zero allocation overhead
 
-use crate::trace::replay::{ReplayEvent, ReplayTrace, TraceMetadata};
+use crate::trace::replay::{CompactRegionId, ReplayEvent, ReplayTrace, TraceMetadata};
 use crate::tracing_compat::{error, warn};
 use crate::types::{RegionId, Severity, TaskId, Time};
 use std::collections::VecDeque;
@@ -227,6 +227,10 @@ pub mod chaos_kind {
     pub const WAKEUP_STORM: u8 = 3;
     /// Budget exhaustion injection.
     pub const BUDGET_EXHAUST: u8 = 4;
+    /// Lab pause of a task or region.
+    pub const PAUSE: u8 = 5;
+    /// Lab resume of a task or region.
+    pub const RESUME: u8 = 6;
 }
 
 // =============================================================================
@@ -643,6 +647,24 @@ impl TraceRecorder {
         self.record_chaos_injection(chaos_kind::BUDGET_EXHAUST, Some(task), 0);
     }
 
+    /// Records a lab pause of a task or region.
+    ///
+    /// Region pauses carry the packed region id as data.
+    #[inline]
+    pub fn record_pause_injection(&mut self, task: Option<TaskId>, region: Option<RegionId>) {
+        let data = region.map_or(0, |region| CompactRegionId::from(region).0);
+        self.record_chaos_injection(chaos_kind::PAUSE, task, data);
+    }
+
+    /// Records a lab resume of a task or region.
+    ///
+    /// Region resumes carry the packed region id as data.
+    #[inline]
+    pub fn record_resume_injection(&mut self, task: Option<TaskId>, region: Option<RegionId>) {
+        let data = region.map_or(0, |region| CompactRegionId::from(region).0);
+        self.record_chaos_injection(chaos_kind::RESUME, task, data);
+    }
+
     // =========================================================================
     // Recording Methods - Wakers
     // =========================================================================
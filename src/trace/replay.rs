@@ -323,7 +323,8 @@ pub enum ReplayEvent {
     // =========================================================================
     /// Chaos was injected.
     ChaosInjection {
-        /// Kind of chaos (0=cancel, 1=delay, 2=io_error, 3=wakeup_storm, 4=budget).
+        /// Kind of chaos (0=cancel, 1=delay, 2=io_error, 3=wakeup_storm, 4=budget,
+        /// 5=pause, 6=resume).
         kind: u8,
         /// Affected task, if any.
         task: Option<CompactTaskId>,
@@ -0,0 +1,805 @@
+//! Spawn-site symbol maps and post-hoc trace annotation.
+//!
+//! Traces name tasks by id, and ids say nothing about which code spawned
+//! them. Labels help, but most spawns never get one. Instead of asking every
+//! call site for a name, the runtime records a [`SpawnSiteId`] for each task
+//! spawned through [`Cx::spawn`](crate::cx::Cx::spawn) or
+//! [`Cx::spawn_in`](crate::cx::Cx::spawn_in): a stable hash of the caller's
+//! file and line. The id lives in the task's context (see
+//! [`TaskRecord::spawn_site`](crate::record::TaskRecord::spawn_site)) and in
+//! a sidecar of the trace buffer
+//! ([`TraceBufferHandle::spawn_sites`](crate::trace::TraceBufferHandle::spawn_sites)),
+//! so traces carry no extra events and their encoding is unchanged.
+//!
+//! A [`SymbolMap`] resolves ids back to names. It is a versioned JSON file,
+//! usually generated from a `build.rs` or a CI step with
+//! [`SymbolMapBuilder::scan_dir`], and shipped next to the traces it
+//! explains. [`annotate`] joins a trace, its spawn sites and a map into
+//! [`TraceAnnotations`], which the analysis tooling accepts in place of raw
+//! task ids:
+//!
+//! ```ignore
+//! let map = SymbolMap::from_json(&std::fs::read_to_string("symbols.json")?)?;
+//! let events = runtime.trace().snapshot();
+//! let annotations = annotate(&events, &runtime.trace().spawn_sites(), &map);
+//! print!("{}", folded_stacks_annotated(&events, &annotations));
+//! ```
+//!
+//! # Stability
+//!
+//! The id is FNV-1a over the source path and the line. The path is cut back
+//! to its last `src`, `tests`, `examples` or `benches` component, so the
+//! same file hashes the same from a workspace checkout, a registry download
+//! or a remapped build directory. Columns are left out: reformatting within a
+//! line keeps the id. Moving a spawn to another line changes it, and so does
+//! any edit that shifts the lines above, which is why the map is regenerated
+//! per build rather than maintained by hand.
+//!
+//! A site missing from the map still annotates, under its hash (see
+//! [`SpawnSiteId`]'s `Display`), so a stale map degrades to stable but
+//! unnamed frames rather than to nothing.
+
+use crate::trace::replay::CompactTaskId;
+use crate::trace::{TraceData, TraceEvent};
+use crate::types::TaskId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::panic::Location;
+use std::path::Path;
+
+/// Current schema version of [`SymbolMap`] JSON files.
+pub const SYMBOL_MAP_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of [`TraceAnnotations`].
+pub const TRACE_ANNOTATIONS_SCHEMA_VERSION: u32 = 1;
+
+/// Path components that start the stable part of a source path.
+const SOURCE_ROOTS: [&str; 4] = ["src", "tests", "examples", "benches"];
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Stable identifier of a spawn or scope call site.
+///
+/// Displays and serializes as 16 lowercase hex digits prefixed with `site:`,
+/// which is also the fallback name of a site missing from the symbol map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct SpawnSiteId(u64);
+
+impl SpawnSiteId {
+    /// Hashes `file` and `line` into a site id.
+    ///
+    /// Backslashes count as separators and everything before the last source
+    /// root component is dropped, so `/build/app/src/main.rs` and
+    /// `src\main.rs` give the same id.
+    #[must_use]
+    pub fn of(file: &str, line: u32) -> Self {
+        let components = || {
+            file.split(['/', '\\'])
+                .filter(|component| !component.is_empty() && *component != ".")
+        };
+        let root = components()
+            .enumerate()
+            .filter(|(_, component)| SOURCE_ROOTS.contains(component))
+            .last()
+            .map_or(0, |(index, _)| index);
+        let mut hash = FNV_OFFSET;
+        let mut write = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+        for (index, component) in components().skip(root).enumerate() {
+            if index > 0 {
+                write(b"/");
+            }
+            write(component.as_bytes());
+        }
+        write(&[0]);
+        write(&line.to_le_bytes());
+        Self(hash)
+    }
+
+    /// Returns the id of `location`.
+    #[must_use]
+    pub fn from_location(location: &Location<'_>) -> Self {
+        Self::of(location.file(), location.line())
+    }
+
+    /// Returns the id of the caller's location.
+    ///
+    /// Inside a `#[track_caller]` function this is the location of the
+    /// outermost untracked caller.
+    #[track_caller]
+    #[must_use]
+    pub fn caller() -> Self {
+        Self::from_location(Location::caller())
+    }
+
+    /// Reconstructs an id from its raw value.
+    #[must_use]
+    pub const fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw hash.
+    #[must_use]
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SpawnSiteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "site:{:016x}", self.0)
+    }
+}
+
+impl std::str::FromStr for SpawnSiteId {
+    type Err = SymbolMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix("site:").unwrap_or(s);
+        u64::from_str_radix(hex, 16)
+            .map(Self)
+            .map_err(|_| SymbolMapError::InvalidSiteId(s.to_string()))
+    }
+}
+
+impl From<SpawnSiteId> for String {
+    fn from(id: SpawnSiteId) -> Self {
+        id.to_string()
+    }
+}
+
+impl TryFrom<String> for SpawnSiteId {
+    type Error = SymbolMapError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// What kind of call a site is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteKind {
+    /// A task spawn (`.spawn(..)`, `.spawn_in(..)`, `spawn!(..)`).
+    Spawn,
+    /// A scope or region entry (`.scope(..)`, `scope!(..)`).
+    Scope,
+}
+
+/// One resolved call site.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolEntry {
+    /// Hash of `file` and `line`.
+    pub id: SpawnSiteId,
+    /// Crate the site belongs to.
+    pub crate_name: String,
+    /// Module path of the file, e.g. `my_app::server::accept`.
+    pub module_path: String,
+    /// Source path as hashed, e.g. `src/server/accept.rs`.
+    pub file: String,
+    /// One-based line of the call.
+    pub line: u32,
+    /// What kind of call the site is.
+    pub kind: SiteKind,
+}
+
+impl SymbolEntry {
+    /// Creates an entry, computing its id from `file` and `line`.
+    #[must_use]
+    pub fn new(
+        crate_name: impl Into<String>,
+        module_path: impl Into<String>,
+        file: impl Into<String>,
+        line: u32,
+        kind: SiteKind,
+    ) -> Self {
+        let file = file.into();
+        Self {
+            id: SpawnSiteId::of(&file, line),
+            crate_name: crate_name.into(),
+            module_path: module_path.into(),
+            file,
+            line,
+            kind,
+        }
+    }
+
+    /// Name used in annotated traces: `module::path@file:line`.
+    ///
+    /// Contains no spaces or semicolons, so it is safe as a folded-stack
+    /// frame.
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        format!("{}@{}:{}", self.module_path, self.file, self.line)
+    }
+}
+
+/// Errors reading a [`SymbolMap`].
+#[derive(Debug, thiserror::Error)]
+pub enum SymbolMapError {
+    /// The JSON could not be parsed.
+    #[error("invalid symbol map JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The map was written by a newer (or unknown) schema.
+    #[error("unsupported symbol map version {found} (supported: 1..={supported})")]
+    UnsupportedVersion {
+        /// Version found in the file.
+        found: u32,
+        /// Newest version this build reads.
+        supported: u32,
+    },
+    /// A site id was not `site:` followed by hex digits.
+    #[error("invalid spawn site id {0:?}")]
+    InvalidSiteId(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SymbolMapFile {
+    version: u32,
+    entries: Vec<SymbolEntry>,
+}
+
+/// Mapping from [`SpawnSiteId`] to the call sites that hash to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolMap {
+    sites: BTreeMap<SpawnSiteId, Vec<SymbolEntry>>,
+}
+
+impl SymbolMap {
+    /// Creates an empty map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry. Re-adding the same file and line is a no-op.
+    pub fn insert(&mut self, entry: SymbolEntry) {
+        let entries = self.sites.entry(entry.id).or_default();
+        if !entries.contains(&entry) {
+            entries.push(entry);
+            entries.sort_by(|a, b| {
+                (&a.crate_name, &a.file, a.line).cmp(&(&b.crate_name, &b.file, b.line))
+            });
+        }
+    }
+
+    /// Adds every entry of `other`, e.g. the map of a dependency.
+    pub fn merge(&mut self, other: Self) {
+        for entry in other.sites.into_values().flatten() {
+            self.insert(entry);
+        }
+    }
+
+    /// Returns the entries that hash to `id`; more than one means a
+    /// collision, typically the same path and line in two crates.
+    #[must_use]
+    pub fn resolve(&self, id: SpawnSiteId) -> &[SymbolEntry] {
+        self.sites.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the display name of `id`, or `None` when the map lacks it.
+    ///
+    /// Colliding entries are joined with `|`.
+    #[must_use]
+    pub fn name(&self, id: SpawnSiteId) -> Option<String> {
+        let entries = self.resolve(id);
+        if entries.is_empty() {
+            return None;
+        }
+        let names: Vec<String> = entries.iter().map(SymbolEntry::display_name).collect();
+        Some(names.join("|"))
+    }
+
+    /// Returns the display name of `id`, falling back to the hash.
+    #[must_use]
+    pub fn name_or_hash(&self, id: SpawnSiteId) -> String {
+        self.name(id).unwrap_or_else(|| id.to_string())
+    }
+
+    /// Number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sites.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if the map has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    /// Iterates over all entries in id order.
+    pub fn entries(&self) -> impl Iterator<Item = &SymbolEntry> {
+        self.sites.values().flatten()
+    }
+
+    /// Serializes the map as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&SymbolMapFile {
+            version: SYMBOL_MAP_SCHEMA_VERSION,
+            entries: self.entries().cloned().collect(),
+        })
+    }
+
+    /// Parses a map written by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self, SymbolMapError> {
+        let file: SymbolMapFile = serde_json::from_str(json)?;
+        if file.version == 0 || file.version > SYMBOL_MAP_SCHEMA_VERSION {
+            return Err(SymbolMapError::UnsupportedVersion {
+                found: file.version,
+                supported: SYMBOL_MAP_SCHEMA_VERSION,
+            });
+        }
+        let mut map = Self::new();
+        for entry in file.entries {
+            map.insert(entry);
+        }
+        Ok(map)
+    }
+}
+
+/// Builds a [`SymbolMap`] by scanning Rust sources for spawn and scope
+/// calls.
+///
+/// The scan is lexical: it looks for `.spawn(`, `.spawn_<suffix>(`,
+/// `.scope(`, `spawn!(` and `scope!(` (turbofish allowed) outside line
+/// comments. When a call continues a method chain that started on an
+/// earlier line, the chain's first line is recorded as well, since that is
+/// where some compilers place the caller location of a method call.
+#[derive(Debug, Clone)]
+pub struct SymbolMapBuilder {
+    crate_name: String,
+    map: SymbolMap,
+}
+
+impl SymbolMapBuilder {
+    /// Creates a builder for `crate_name` (dashes become underscores in
+    /// module paths).
+    #[must_use]
+    pub fn new(crate_name: impl Into<String>) -> Self {
+        Self {
+            crate_name: crate_name.into(),
+            map: SymbolMap::new(),
+        }
+    }
+
+    /// Scans one file. `file` is its path relative to the crate root, e.g.
+    /// `src/server.rs`.
+    pub fn scan_source(&mut self, file: &str, source: &str) -> &mut Self {
+        let module_path = module_path_of(&self.crate_name, file);
+        let lines: Vec<&str> = source.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            let code = line.split("//").next().unwrap_or_default();
+            let Some(kind) = call_kind(code) else {
+                continue;
+            };
+            let mut record = |index: usize| {
+                let line = u32::try_from(index + 1).unwrap_or(u32::MAX);
+                let entry = SymbolEntry::new(&self.crate_name, &module_path, file, line, kind);
+                self.map.insert(entry);
+            };
+            record(index);
+            if code.trim_start().starts_with('.') {
+                let start = (0..index)
+                    .rev()
+                    .find(|&prev| !lines[prev].trim_start().starts_with('.'))
+                    .unwrap_or(index);
+                if start != index {
+                    record(start);
+                }
+            }
+        }
+        self
+    }
+
+    /// Scans every `.rs` file under `root/src` (and `tests`, `examples`,
+    /// `benches` when present), in sorted order.
+    pub fn scan_dir(&mut self, root: &Path) -> io::Result<&mut Self> {
+        for dir in SOURCE_ROOTS {
+            let base = root.join(dir);
+            if !base.is_dir() {
+                continue;
+            }
+            let mut files = Vec::new();
+            collect_rust_files(&base, &mut files)?;
+            files.sort();
+            for path in files {
+                let source = std::fs::read_to_string(&path)?;
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                self.scan_source(&relative, &source);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the map built so far.
+    #[must_use]
+    pub fn build(&self) -> SymbolMap {
+        self.map.clone()
+    }
+}
+
+fn collect_rust_files(dir: &Path, out: &mut Vec<std::path::PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rust_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Derives `crate::a::b` from `src/a/b.rs` (`lib.rs`, `main.rs` and
+/// `mod.rs` name their parent). Files outside `src` are their own crate
+/// roots, so `tests/smoke.rs` becomes `smoke`.
+fn module_path_of(crate_name: &str, file: &str) -> String {
+    let crate_ident = crate_name.replace('-', "_");
+    let parts: Vec<&str> = file
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    let root = parts.iter().rposition(|part| SOURCE_ROOTS.contains(part));
+    let in_src = root.is_some_and(|root| parts[root] == "src");
+    let mut modules: Vec<&str> = parts[root.map_or(0, |root| root + 1)..].to_vec();
+    if let Some(last) = modules.last_mut() {
+        *last = last.strip_suffix(".rs").unwrap_or(last);
+    }
+    let parent_named = matches!(modules.last(), Some(&("lib" | "main" | "mod")));
+    if parent_named && (in_src || modules.len() > 1) {
+        modules.pop();
+    }
+    if in_src {
+        modules.insert(0, &crate_ident);
+    }
+    if modules.is_empty() {
+        crate_ident
+    } else {
+        modules.join("::")
+    }
+}
+
+/// Returns the kind of the first spawn or scope call in `code`.
+fn call_kind(code: &str) -> Option<SiteKind> {
+    for (name, kind) in [("spawn", SiteKind::Spawn), ("scope", SiteKind::Scope)] {
+        for (start, _) in code.match_indices(name) {
+            let before = code[..start].chars().next_back();
+            let rest = &code[start + name.len()..];
+            if rest.starts_with("!(") && !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+            {
+                return Some(kind);
+            }
+            // `.spawn(`, `.spawn::<T>(` or `.spawn_suffix(`.
+            let tail = rest.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+            let whole_word = tail.len() == rest.len() || rest.starts_with('_');
+            if before == Some('.')
+                && whole_word
+                && (tail.starts_with('(') || tail.starts_with("::<"))
+            {
+                return Some(kind);
+            }
+        }
+    }
+    None
+}
+
+/// Resolved name of one task in an annotated trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskAnnotation {
+    /// The task.
+    pub task: TaskId,
+    /// Where it was spawned.
+    pub site: SpawnSiteId,
+    /// Symbol-map name, or the site hash when unresolved.
+    pub name: String,
+    /// Whether the symbol map knew the site.
+    pub resolved: bool,
+}
+
+/// Sidecar of resolved task names for one trace, produced by [`annotate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceAnnotations {
+    /// Schema version ([`TRACE_ANNOTATIONS_SCHEMA_VERSION`]).
+    pub version: u32,
+    /// One entry per task with a known spawn site, sorted by task.
+    pub tasks: Vec<TaskAnnotation>,
+}
+
+impl TraceAnnotations {
+    /// Returns the name of `task`, if it has one.
+    #[must_use]
+    pub fn name(&self, task: TaskId) -> Option<&str> {
+        self.tasks
+            .binary_search_by_key(&task, |annotation| annotation.task)
+            .ok()
+            .map(|index| self.tasks[index].name.as_str())
+    }
+
+    /// Returns all names keyed by task.
+    #[must_use]
+    pub fn names(&self) -> BTreeMap<TaskId, String> {
+        self.tasks
+            .iter()
+            .map(|annotation| (annotation.task, annotation.name.clone()))
+            .collect()
+    }
+
+    /// Returns all names keyed by raw [`CompactTaskId`], the form
+    /// [`DiffOptions`](crate::trace::analysis::DiffOptions) labels use.
+    #[must_use]
+    pub fn compact_labels(&self) -> BTreeMap<u64, String> {
+        self.tasks
+            .iter()
+            .map(|annotation| (CompactTaskId::from(annotation.task).0, annotation.name.clone()))
+            .collect()
+    }
+
+    /// Number of tasks whose site the symbol map did not know.
+    #[must_use]
+    pub fn unresolved(&self) -> usize {
+        self.tasks.iter().filter(|annotation| !annotation.resolved).count()
+    }
+
+    /// Serializes the annotations as JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Resolves the spawn site of every task in `trace` through `map`.
+///
+/// `sites` comes from the trace buffer that recorded `trace`
+/// ([`TraceBufferHandle::spawn_sites`](crate::trace::TraceBufferHandle::spawn_sites)).
+/// Tasks without a recorded site are left out; sites missing from `map` are
+/// named by their hash and marked unresolved. The trace itself is not
+/// modified.
+#[must_use]
+pub fn annotate(
+    trace: &[TraceEvent],
+    sites: &BTreeMap<TaskId, SpawnSiteId>,
+    map: &SymbolMap,
+) -> TraceAnnotations {
+    let mut tasks = BTreeMap::new();
+    for event in trace {
+        let TraceData::Task { task, .. } = event.data else {
+            continue;
+        };
+        if tasks.contains_key(&task) {
+            continue;
+        }
+        if let Some(&site) = sites.get(&task) {
+            let name = map.name(site);
+            tasks.insert(
+                task,
+                TaskAnnotation {
+                    task,
+                    site,
+                    resolved: name.is_some(),
+                    name: name.unwrap_or_else(|| site.to_string()),
+                },
+            );
+        }
+    }
+    TraceAnnotations {
+        version: TRACE_ANNOTATIONS_SCHEMA_VERSION,
+        tasks: tasks.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::types::{RegionId, Time};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn task(n: u32) -> TaskId {
+        TaskId::new_for_test(n, 0)
+    }
+
+    #[test]
+    fn site_hash_is_stable_across_builds_and_paths() {
+        init_test("site_hash_is_stable_across_builds_and_paths");
+        // Pinned: changing the hash invalidates every published symbol map.
+        let id = SpawnSiteId::of("src/main.rs", 42);
+        crate::assert_with_log!(
+            id.as_u64() == 0x1d72_3519_3ec6_8e1e,
+            "pinned hash",
+            0x1d72_3519_3ec6_8e1e_u64,
+            id.as_u64()
+        );
+        for path in [
+            "./src/main.rs",
+            "/home/ci/work/app/src/main.rs",
+            "C:\\work\\app\\src\\main.rs",
+            "/cargo/registry/src/index-1949cf8c/app-1.2.0/src/main.rs",
+        ] {
+            let other = SpawnSiteId::of(path, 42);
+            crate::assert_with_log!(other == id, "same file, same id", id, other);
+        }
+        let moved = SpawnSiteId::of("src/main.rs", 43);
+        crate::assert_with_log!(moved != id, "line matters", "different", moved);
+
+        let text = id.to_string();
+        crate::assert_with_log!(text == "site:1d7235193ec68e1e", "display", "site:..", text);
+        let parsed: SpawnSiteId = text.parse().expect("parse");
+        crate::assert_with_log!(parsed == id, "round trip", id, parsed);
+        let bad = "site:xyz".parse::<SpawnSiteId>();
+        crate::assert_with_log!(bad.is_err(), "rejects non-hex", true, bad.is_err());
+
+        // Capturing a site is a hash of a few dozen bytes into a `u64`.
+        let here = SpawnSiteId::caller();
+        let expected = SpawnSiteId::of(file!(), line!() - 1);
+        crate::assert_with_log!(here == expected, "caller location", expected, here);
+        let size = std::mem::size_of::<Option<SpawnSiteId>>();
+        crate::assert_with_log!(size <= 16, "small", "<= 16", size);
+        crate::test_complete!("site_hash_is_stable_across_builds_and_paths");
+    }
+
+    #[test]
+    fn scanner_finds_spawn_and_scope_sites() {
+        init_test("scanner_finds_spawn_and_scope_sites");
+        let source = "\
+fn serve(cx: &Cx) {
+    let handle = cx.spawn(|cx| accept(cx));
+    // cx.spawn(|cx| commented_out(cx));
+    let respawned = pool.respawn(1);
+    let count = cx.spawned_count();
+    cx.scope(|scope| async move {})
+    let blocking = cx.spawn_blocking::<u32>(work);
+    let chained = builder
+        .name(\"worker\")
+        .spawn(|cx| work(cx));
+    spawn!(cx, work);
+}
+";
+        let map = SymbolMapBuilder::new("my-app")
+            .scan_source("src/net/server.rs", source)
+            .build();
+        let mut found: Vec<(u32, SiteKind)> =
+            map.entries().map(|entry| (entry.line, entry.kind)).collect();
+        found.sort();
+        let expected = vec![
+            (2, SiteKind::Spawn),
+            (6, SiteKind::Scope),
+            (7, SiteKind::Spawn),
+            (8, SiteKind::Spawn),
+            (10, SiteKind::Spawn),
+            (11, SiteKind::Spawn),
+        ];
+        crate::assert_with_log!(found == expected, "sites", expected, found);
+
+        let entry = &map.resolve(SpawnSiteId::of("src/net/server.rs", 2))[0];
+        crate::assert_with_log!(
+            entry.module_path == "my_app::net::server",
+            "module path",
+            "my_app::net::server",
+            entry.module_path
+        );
+        let name = entry.display_name();
+        let expected = "my_app::net::server@src/net/server.rs:2";
+        crate::assert_with_log!(name == expected, "display name", expected, name);
+
+        for (file, module) in [
+            ("src/lib.rs", "my_app"),
+            ("src/net/mod.rs", "my_app::net"),
+            ("tests/smoke.rs", "smoke"),
+            ("examples/echo/main.rs", "echo"),
+        ] {
+            let path = module_path_of("my-app", file);
+            crate::assert_with_log!(path == module, "module path of file", module, path);
+        }
+        crate::test_complete!("scanner_finds_spawn_and_scope_sites");
+    }
+
+    #[test]
+    fn symbol_map_json_is_versioned() {
+        init_test("symbol_map_json_is_versioned");
+        let mut map = SymbolMap::new();
+        map.insert(SymbolEntry::new("a", "a::jobs", "src/jobs.rs", 10, SiteKind::Spawn));
+        map.insert(SymbolEntry::new("b", "b::jobs", "src/jobs.rs", 10, SiteKind::Spawn));
+        map.insert(SymbolEntry::new("a", "a", "src/lib.rs", 3, SiteKind::Scope));
+        let json = map.to_json().expect("serialize");
+        crate::assert_with_log!(json.contains("\"version\": 1"), "version", 1, json);
+        let parsed = SymbolMap::from_json(&json).expect("parse");
+        crate::assert_with_log!(parsed == map, "round trip", map, parsed);
+
+        // The same path and line in two crates collide; both names show.
+        let name = parsed.name(SpawnSiteId::of("src/jobs.rs", 10));
+        let expected = Some("a::jobs@src/jobs.rs:10|b::jobs@src/jobs.rs:10".to_string());
+        crate::assert_with_log!(name == expected, "collision", expected, name);
+
+        let newer = json.replace("\"version\": 1", "\"version\": 2");
+        let err = SymbolMap::from_json(&newer).expect_err("newer schema");
+        let unsupported = matches!(err, SymbolMapError::UnsupportedVersion { found: 2, .. });
+        crate::assert_with_log!(unsupported, "rejects newer", true, err);
+        crate::test_complete!("symbol_map_json_is_versioned");
+    }
+
+    #[test]
+    fn annotate_resolves_fixture_and_falls_back_to_hash() {
+        init_test("annotate_resolves_fixture_and_falls_back_to_hash");
+        let region = RegionId::new_for_test(0, 0);
+        let at = Time::ZERO;
+        let trace = vec![
+            TraceEvent::spawn(0, at, task(1), region),
+            TraceEvent::spawn(1, at, task(2), region),
+            TraceEvent::spawn(2, at, task(3), region),
+            TraceEvent::poll(3, at, task(1), region),
+            TraceEvent::complete(4, at, task(1), region),
+        ];
+        let known = SymbolEntry::new("app", "app::io", "src/io.rs", 12, SiteKind::Spawn);
+        let stale = SpawnSiteId::of("src/io.rs", 13);
+        let mut map = SymbolMap::new();
+        map.insert(known.clone());
+        // Task 3 has no recorded site; task 9 is not in the trace.
+        let sites = BTreeMap::from([(task(1), known.id), (task(2), stale), (task(9), known.id)]);
+
+        let annotations = annotate(&trace, &sites, &map);
+        let names = annotations.names();
+        let expected = BTreeMap::from([
+            (task(1), "app::io@src/io.rs:12".to_string()),
+            (task(2), stale.to_string()),
+        ]);
+        crate::assert_with_log!(names == expected, "names", expected, names);
+        let unresolved = annotations.unresolved();
+        crate::assert_with_log!(unresolved == 1, "one unresolved", 1, unresolved);
+        let missing = annotations.name(task(3));
+        crate::assert_with_log!(missing.is_none(), "no site, no name", "None", missing);
+
+        let labels = annotations.compact_labels();
+        let key = CompactTaskId::from(task(1)).0;
+        let label = labels.get(&key).map(String::as_str);
+        crate::assert_with_log!(
+            label == Some("app::io@src/io.rs:12"),
+            "diff labels",
+            "app::io@src/io.rs:12",
+            label
+        );
+
+        let json = annotations.to_json().expect("serialize");
+        let back: TraceAnnotations = serde_json::from_str(&json).expect("parse");
+        crate::assert_with_log!(back == annotations, "sidecar round trip", annotations, back);
+        crate::test_complete!("annotate_resolves_fixture_and_falls_back_to_hash");
+    }
+
+    #[test]
+    fn cx_spawn_records_call_site() {
+        init_test("cx_spawn_records_call_site");
+        let ((recorded, expected, sidecar), _) =
+            crate::lab::run_async_under_lab(7, |cx| async move {
+                let expected = SpawnSiteId::of(file!(), line!() + 1);
+                let mut handle = cx.spawn(|c| async move { c.spawn_site() }).expect("spawn");
+                let recorded = handle.join(&cx).await.expect("join");
+                let child = handle.task_id();
+                let sidecar = cx
+                    .trace_buffer()
+                    .and_then(|trace| trace.spawn_sites().get(&child).copied());
+                (recorded, expected, sidecar)
+            });
+        crate::assert_with_log!(recorded == Some(expected), "cx site", expected, recorded);
+        crate::assert_with_log!(sidecar == Some(expected), "trace sidecar", expected, sidecar);
+        crate::test_complete!("cx_spawn_records_call_site");
+    }
+}
@@ -0,0 +1,1433 @@
+//! Congestion-controlled symbol pacing.
+//!
+//! A [`SymbolPacer`] holds the symbols queued for one path and releases them
+//! no faster than its [`CongestionController`] allows: every release pushes
+//! the next pacing slot out by the symbol's size at the controller's pacing
+//! rate, and no symbol is released while the bytes in flight would exceed
+//! the congestion window. Receiver feedback drives the controller through
+//! [`SymbolPacer::on_ack`] and [`SymbolPacer::on_loss`]. The pacer never
+//! drops a symbol: a symbol leaves the queue only by being released.
+//!
+//! Two controllers are provided, selected by [`CongestionAlgorithm`] in
+//! [`TransportConfig`](crate::config::TransportConfig):
+//!
+//! - [`AimdController`] grows its window by one symbol per round trip (after
+//!   a slow start) and cuts it multiplicatively, at most once per round trip,
+//!   on loss.
+//! - [`BbrLikeController`] models the path from delivery-rate and RTT
+//!   samples. It paces at the windowed-max delivery rate (btlbw), cycling its
+//!   gain to probe for more, and sizes its window from the bandwidth-delay
+//!   product over the windowed-min RTT (rtprop). Loss does not move its rate;
+//!   a burst losing more than a fifth of a round's bytes trims the window to
+//!   one BDP for the rest of that round.
+//!
+//! Multipath transports run one pacer, and so one controller, per path.
+//!
+//! Nothing here reads a clock: every call takes the runtime's [`Time`], and
+//! [`SymbolPacer::release`] sleeps on the runtime timer, so lab runs against
+//! a scripted link make the same decisions on every replay. Controller state
+//! is published as gauges once [`SymbolPacer::register_metrics`] is called,
+//! and every `event_sample_every`-th decision is recorded as a
+//! [`CongestionEvent`] and logged at debug level.
+
+use crate::cx::Cx;
+use crate::observability::exposition::GaugeSeries;
+use crate::observability::{MetricOpts, MetricsRegistry, RegistryError};
+use crate::transport::aggregator::PathId;
+use crate::types::Time;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+/// AIMD pacing gain in slow start, in permille of cwnd per smoothed RTT.
+const SLOW_START_GAIN_PERMILLE: u64 = 2000;
+/// AIMD pacing gain in congestion avoidance.
+const AVOIDANCE_GAIN_PERMILLE: u64 = 1250;
+/// BBR-like startup gain, 2/ln 2.
+const HIGH_GAIN_PERMILLE: u64 = 2885;
+/// BBR-like drain gain, the inverse of the startup gain.
+const DRAIN_GAIN_PERMILLE: u64 = 347;
+/// BBR-like pacing gain cycle in `probe_bw`, one phase per rtprop.
+const PROBE_BW_GAINS_PERMILLE: [u64; 8] = [1250, 750, 1000, 1000, 1000, 1000, 1000, 1000];
+/// BBR-like window gain in `probe_bw`.
+const PROBE_BW_CWND_GAIN_PERMILLE: u64 = 2000;
+/// Rounds over which the bottleneck bandwidth filter keeps its maximum.
+const BTLBW_WINDOW_ROUNDS: u64 = 10;
+/// How long an rtprop sample is kept without being matched again.
+const RTPROP_WINDOW: Duration = Duration::from_secs(10);
+/// Startup ends after this many rounds without 25% btlbw growth.
+const FULL_BW_ROUNDS: u32 = 3;
+/// Drain ends after this many rounds even if the queue never looks empty.
+const MAX_DRAIN_ROUNDS: u32 = 8;
+/// Sampled events kept by a pacer before the oldest are dropped.
+const MAX_RECORDED_EVENTS: usize = 1024;
+
+/// Congestion control algorithm run for each path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CongestionAlgorithm {
+    /// Additive increase, multiplicative decrease on loss.
+    #[default]
+    Aimd,
+    /// Delay-based model of bottleneck bandwidth and propagation delay.
+    BbrLike,
+}
+
+impl CongestionAlgorithm {
+    /// Returns the stable name used in configuration, events and logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Aimd => "aimd",
+            Self::BbrLike => "bbr_like",
+        }
+    }
+}
+
+/// Congestion control settings shared by every path of a transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CongestionControlConfig {
+    /// Controller run for each path.
+    pub algorithm: CongestionAlgorithm,
+    /// Typical symbol size on the wire, in bytes. Sets the additive increase
+    /// step and the window floor.
+    pub symbol_bytes: u32,
+    /// Congestion window before any feedback, in symbols.
+    pub initial_cwnd_symbols: u32,
+    /// Smallest congestion window, in symbols.
+    pub min_cwnd_symbols: u32,
+    /// Pacing rate before the first RTT sample, in bytes per second.
+    pub initial_pacing_rate: u64,
+    /// Upper bound on the pacing rate, in bytes per second.
+    pub max_pacing_rate: u64,
+    /// Share of the AIMD window kept on loss, in permille.
+    pub aimd_decrease_permille: u16,
+    /// Record every n-th controller decision as a [`CongestionEvent`];
+    /// 0 records none.
+    pub event_sample_every: u32,
+}
+
+impl Default for CongestionControlConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CongestionAlgorithm::default(),
+            symbol_bytes: 1280,
+            initial_cwnd_symbols: 10,
+            min_cwnd_symbols: 4,
+            initial_pacing_rate: 1_250_000,  // 10 Mbps
+            max_pacing_rate: 12_500_000_000, // 100 Gbps
+            aimd_decrease_permille: 500,
+            event_sample_every: 64,
+        }
+    }
+}
+
+impl CongestionControlConfig {
+    /// Checks that the settings are consistent.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.symbol_bytes == 0 {
+            return Err("congestion symbol_bytes must be non-zero");
+        }
+        if self.min_cwnd_symbols == 0 {
+            return Err("congestion min_cwnd_symbols must be non-zero");
+        }
+        if self.initial_cwnd_symbols < self.min_cwnd_symbols {
+            return Err("congestion initial_cwnd_symbols must be >= min_cwnd_symbols");
+        }
+        if self.initial_pacing_rate == 0 || self.initial_pacing_rate > self.max_pacing_rate {
+            return Err("congestion initial_pacing_rate must be in 1..=max_pacing_rate");
+        }
+        if self.aimd_decrease_permille == 0 || self.aimd_decrease_permille >= 1000 {
+            return Err("congestion aimd_decrease_permille must be in 1..1000");
+        }
+        Ok(())
+    }
+
+    /// Creates a fresh controller running the configured algorithm.
+    #[must_use]
+    pub fn controller(&self) -> Box<dyn CongestionController> {
+        match self.algorithm {
+            CongestionAlgorithm::Aimd => Box::new(AimdController::new(self)),
+            CongestionAlgorithm::BbrLike => Box::new(BbrLikeController::new(self)),
+        }
+    }
+
+    /// Creates the pacer for one path, with its own controller.
+    #[must_use]
+    pub fn pacer<T>(&self, path: PathId) -> SymbolPacer<T> {
+        SymbolPacer::new(path, self.controller(), self.event_sample_every)
+    }
+}
+
+/// Symbols the receiver reported lost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LossInfo {
+    /// Bytes of the lost symbols.
+    pub lost_bytes: u64,
+}
+
+/// Decides how fast, and how much, one path may send.
+///
+/// Implementations see time only through the `now` they are given, so the
+/// same feedback in the same order yields the same decisions.
+pub trait CongestionController: fmt::Debug + Send {
+    /// Stable algorithm name used in events and logs.
+    fn name(&self) -> &'static str;
+
+    /// Records an acknowledgement of `delivered_bytes`, sampled at
+    /// `rtt_sample` from send to acknowledgement.
+    fn on_ack(&mut self, now: Time, rtt_sample: Duration, delivered_bytes: u64);
+
+    /// Records symbols the receiver reported lost.
+    fn on_loss(&mut self, now: Time, loss: LossInfo);
+
+    /// Current pacing rate, in bytes per second.
+    fn pacing_rate(&self) -> u64;
+
+    /// Current congestion window, in bytes.
+    fn cwnd(&self) -> u64;
+
+    /// Current phase of the algorithm, for events and logs.
+    fn phase(&self) -> &'static str;
+
+    /// Bottleneck bandwidth estimate in bytes per second, if the controller
+    /// keeps one.
+    fn btlbw(&self) -> Option<u64> {
+        None
+    }
+
+    /// Round-trip propagation delay estimate, if the controller keeps one.
+    fn rtprop(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Point-in-time controller state of one path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CongestionSnapshot {
+    /// Controller name.
+    pub algorithm: &'static str,
+    /// Controller phase.
+    pub phase: &'static str,
+    /// Pacing rate, in bytes per second.
+    pub pacing_rate: u64,
+    /// Congestion window, in bytes.
+    pub cwnd: u64,
+    /// Bottleneck bandwidth estimate, in bytes per second.
+    pub btlbw: Option<u64>,
+    /// Propagation delay estimate.
+    pub rtprop: Option<Duration>,
+    /// Bytes released and not yet acknowledged or reported lost.
+    pub bytes_in_flight: u64,
+}
+
+/// Feedback that led to a controller decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CongestionCause {
+    /// An acknowledgement.
+    Ack,
+    /// A loss report.
+    Loss,
+}
+
+impl CongestionCause {
+    /// Returns the stable name used in logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ack => "ack",
+            Self::Loss => "loss",
+        }
+    }
+}
+
+/// One sampled controller decision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CongestionEvent {
+    /// Path the controller belongs to.
+    pub path: PathId,
+    /// Runtime time of the feedback.
+    pub at: Time,
+    /// Decision count at which the event was sampled, starting at 1.
+    pub decision: u64,
+    /// Feedback that led to the decision.
+    pub cause: CongestionCause,
+    /// Controller state after the decision.
+    pub state: CongestionSnapshot,
+}
+
+impl CongestionEvent {
+    fn emit(&self) {
+        crate::tracing_compat::debug!(
+            path = self.path.0,
+            decision = self.decision,
+            cause = self.cause.as_str(),
+            algorithm = self.state.algorithm,
+            phase = self.state.phase,
+            pacing_rate = self.state.pacing_rate,
+            cwnd = self.state.cwnd,
+            btlbw = ?self.state.btlbw,
+            rtprop = ?self.state.rtprop,
+            bytes_in_flight = self.state.bytes_in_flight,
+            "congestion control decision"
+        );
+    }
+}
+
+/// Additive-increase, multiplicative-decrease window controller.
+///
+/// Slow start grows the window by the bytes acknowledged until the first
+/// loss; after that it grows by one symbol per window acknowledged. A loss
+/// keeps `aimd_decrease_permille` of the window, once per smoothed RTT. The
+/// pacing rate spreads the window over the smoothed RTT with some headroom.
+#[derive(Debug, Clone)]
+pub struct AimdController {
+    symbol_bytes: u64,
+    min_cwnd: u64,
+    initial_pacing_rate: u64,
+    max_pacing_rate: u64,
+    decrease_permille: u64,
+    cwnd: u64,
+    ssthresh: u64,
+    /// Bytes acknowledged toward the next additive step.
+    acked_toward_step: u64,
+    srtt: Option<Duration>,
+    min_rtt: Option<Duration>,
+    /// Losses reported before this time belong to the last reduction.
+    recovery_until: Time,
+}
+
+impl AimdController {
+    /// Creates a controller in slow start.
+    #[must_use]
+    pub fn new(config: &CongestionControlConfig) -> Self {
+        let symbol_bytes = u64::from(config.symbol_bytes.max(1));
+        let min_cwnd_symbols = config.min_cwnd_symbols.max(1);
+        Self {
+            symbol_bytes,
+            min_cwnd: symbol_bytes * u64::from(min_cwnd_symbols),
+            initial_pacing_rate: config.initial_pacing_rate.max(1),
+            max_pacing_rate: config.max_pacing_rate.max(1),
+            decrease_permille: u64::from(config.aimd_decrease_permille.clamp(1, 999)),
+            cwnd: symbol_bytes * u64::from(config.initial_cwnd_symbols.max(min_cwnd_symbols)),
+            ssthresh: u64::MAX,
+            acked_toward_step: 0,
+            srtt: None,
+            min_rtt: None,
+            recovery_until: Time::ZERO,
+        }
+    }
+
+    const fn in_slow_start(&self) -> bool {
+        self.cwnd < self.ssthresh
+    }
+}
+
+impl CongestionController for AimdController {
+    fn name(&self) -> &'static str {
+        CongestionAlgorithm::Aimd.as_str()
+    }
+
+    fn on_ack(&mut self, _now: Time, rtt_sample: Duration, delivered_bytes: u64) {
+        self.srtt = Some(
+            self.srtt
+                .map_or(rtt_sample, |srtt| (srtt * 7 + rtt_sample) / 8),
+        );
+        self.min_rtt = Some(self.min_rtt.map_or(rtt_sample, |min| min.min(rtt_sample)));
+        if self.in_slow_start() {
+            self.cwnd = self.cwnd.saturating_add(delivered_bytes);
+        } else {
+            self.acked_toward_step = self.acked_toward_step.saturating_add(delivered_bytes);
+            if self.acked_toward_step >= self.cwnd {
+                self.acked_toward_step -= self.cwnd;
+                self.cwnd = self.cwnd.saturating_add(self.symbol_bytes);
+            }
+        }
+    }
+
+    fn on_loss(&mut self, now: Time, _loss: LossInfo) {
+        if now < self.recovery_until {
+            return;
+        }
+        let cut = apply_gain(self.cwnd, self.decrease_permille);
+        self.cwnd = cut.max(self.min_cwnd);
+        self.ssthresh = self.cwnd;
+        self.acked_toward_step = 0;
+        self.recovery_until = now + self.srtt.unwrap_or(Duration::ZERO);
+    }
+
+    fn pacing_rate(&self) -> u64 {
+        let Some(srtt) = self.srtt else {
+            return self.initial_pacing_rate;
+        };
+        let gain = if self.in_slow_start() {
+            SLOW_START_GAIN_PERMILLE
+        } else {
+            AVOIDANCE_GAIN_PERMILLE
+        };
+        bytes_per_sec(apply_gain(self.cwnd, gain), duration_nanos(srtt))
+            .clamp(1, self.max_pacing_rate)
+    }
+
+    fn cwnd(&self) -> u64 {
+        self.cwnd
+    }
+
+    fn phase(&self) -> &'static str {
+        if self.in_slow_start() {
+            "slow_start"
+        } else {
+            "congestion_avoidance"
+        }
+    }
+
+    fn rtprop(&self) -> Option<Duration> {
+        self.min_rtt
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BbrMode {
+    Startup,
+    Drain,
+    ProbeBw,
+}
+
+/// Delay-based controller modelled on BBR.
+///
+/// Delivery-rate samples are the bytes acknowledged over the last rtprop;
+/// their per-round maximum over ten rounds is the bottleneck bandwidth
+/// (btlbw). The minimum RTT over ten seconds is the propagation delay
+/// (rtprop); a round is one rtprop. Startup paces at 2/ln 2 times btlbw
+/// until btlbw stops growing by a quarter for three rounds, drain paces
+/// below btlbw until an RTT sample shows the queue gone, and `probe_bw`
+/// cycles its gain through 5/4, 3/4 and six rounds at 1.
+#[derive(Debug, Clone)]
+pub struct BbrLikeController {
+    min_cwnd: u64,
+    max_pacing_rate: u64,
+    mode: BbrMode,
+    /// `(round, max delivery rate)` for the rounds in the btlbw window.
+    btlbw_filter: VecDeque<(u64, u64)>,
+    rtprop: Option<Duration>,
+    rtprop_stamp: Time,
+    delivered: u64,
+    /// `(ack time, total delivered)` covering the last rtprop.
+    delivery_history: VecDeque<(Time, u64)>,
+    round: u64,
+    round_start: Time,
+    round_delivered: u64,
+    round_lost: u64,
+    /// Set by a loss burst; holds the window to one BDP until the round ends.
+    loss_trim: bool,
+    full_bw: u64,
+    full_bw_rounds: u32,
+    drain_rounds: u32,
+    cycle_index: usize,
+    cycle_stamp: Time,
+    pacing_rate: u64,
+    cwnd: u64,
+}
+
+impl BbrLikeController {
+    /// Creates a controller in startup.
+    #[must_use]
+    pub fn new(config: &CongestionControlConfig) -> Self {
+        let symbol_bytes = u64::from(config.symbol_bytes.max(1));
+        let min_cwnd_symbols = config.min_cwnd_symbols.max(1);
+        let max_pacing_rate = config.max_pacing_rate.max(1);
+        Self {
+            min_cwnd: symbol_bytes * u64::from(min_cwnd_symbols),
+            max_pacing_rate,
+            mode: BbrMode::Startup,
+            btlbw_filter: VecDeque::new(),
+            rtprop: None,
+            rtprop_stamp: Time::ZERO,
+            delivered: 0,
+            delivery_history: VecDeque::new(),
+            round: 0,
+            round_start: Time::ZERO,
+            round_delivered: 0,
+            round_lost: 0,
+            loss_trim: false,
+            full_bw: 0,
+            full_bw_rounds: 0,
+            drain_rounds: 0,
+            cycle_index: 0,
+            cycle_stamp: Time::ZERO,
+            pacing_rate: config.initial_pacing_rate.clamp(1, max_pacing_rate),
+            cwnd: symbol_bytes * u64::from(config.initial_cwnd_symbols.max(min_cwnd_symbols)),
+        }
+    }
+
+    fn btlbw_estimate(&self) -> u64 {
+        self.btlbw_filter
+            .iter()
+            .map(|&(_, rate)| rate)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Updates rtprop with `rtt_sample` and returns the estimate.
+    fn update_rtprop(&mut self, now: Time, rtt_sample: Duration) -> Duration {
+        let expired = now.duration_since(self.rtprop_stamp) > duration_nanos(RTPROP_WINDOW);
+        match self.rtprop {
+            Some(rtprop) if rtt_sample > rtprop && !expired => rtprop,
+            _ => {
+                self.rtprop = Some(rtt_sample);
+                self.rtprop_stamp = now;
+                rtt_sample
+            }
+        }
+    }
+
+    /// Takes a delivery-rate sample over the last `window` nanoseconds.
+    fn sample_delivery_rate(&mut self, now: Time, window: u64) {
+        let window_start = now.saturating_sub_nanos(window);
+        while self
+            .delivery_history
+            .get(1)
+            .is_some_and(|&(at, _)| at <= window_start)
+        {
+            self.delivery_history.pop_front();
+        }
+        let Some(&(oldest_at, oldest_delivered)) = self.delivery_history.front() else {
+            return;
+        };
+        let elapsed = now.duration_since(oldest_at);
+        if elapsed == 0 || elapsed < window / 2 {
+            return;
+        }
+        let rate = bytes_per_sec(self.delivered - oldest_delivered, elapsed);
+        match self.btlbw_filter.back_mut() {
+            Some((round, max)) if *round == self.round => *max = (*max).max(rate),
+            _ => self.btlbw_filter.push_back((self.round, rate)),
+        }
+        while self
+            .btlbw_filter
+            .front()
+            .is_some_and(|&(round, _)| round + BTLBW_WINDOW_ROUNDS <= self.round)
+        {
+            self.btlbw_filter.pop_front();
+        }
+    }
+
+    fn start_round(&mut self, now: Time, rtt_sample: Duration, rtprop: Duration, btlbw: u64) {
+        match self.mode {
+            BbrMode::Startup => {
+                if btlbw >= self.full_bw.saturating_mul(5) / 4 {
+                    self.full_bw = btlbw;
+                    self.full_bw_rounds = 0;
+                } else {
+                    self.full_bw_rounds += 1;
+                    if self.full_bw_rounds >= FULL_BW_ROUNDS {
+                        self.mode = BbrMode::Drain;
+                        self.drain_rounds = 0;
+                    }
+                }
+            }
+            BbrMode::Drain => {
+                self.drain_rounds += 1;
+                if rtt_sample <= rtprop + rtprop / 4 || self.drain_rounds >= MAX_DRAIN_ROUNDS {
+                    self.mode = BbrMode::ProbeBw;
+                    self.cycle_index = 0;
+                    self.cycle_stamp = now;
+                }
+            }
+            BbrMode::ProbeBw => {}
+        }
+    }
+
+    /// Pacing and window gains of the current mode.
+    const fn gains(&self) -> (u64, u64) {
+        match self.mode {
+            BbrMode::Startup => (HIGH_GAIN_PERMILLE, HIGH_GAIN_PERMILLE),
+            BbrMode::Drain => (DRAIN_GAIN_PERMILLE, HIGH_GAIN_PERMILLE),
+            BbrMode::ProbeBw => (
+                PROBE_BW_GAINS_PERMILLE[self.cycle_index],
+                PROBE_BW_CWND_GAIN_PERMILLE,
+            ),
+        }
+    }
+
+    fn update_model(&mut self, btlbw: u64) {
+        let Some(rtprop) = self.rtprop else {
+            return;
+        };
+        if btlbw == 0 {
+            return;
+        }
+        let (pacing_gain, cwnd_gain) = self.gains();
+        let mut rate = apply_gain(btlbw, pacing_gain);
+        if self.mode == BbrMode::Startup {
+            // Startup never slows down on a low early sample.
+            rate = rate.max(self.pacing_rate);
+        }
+        self.pacing_rate = rate.clamp(1, self.max_pacing_rate);
+        let bdp = bdp_bytes(btlbw, rtprop);
+        let cwnd = if self.loss_trim {
+            bdp
+        } else {
+            apply_gain(bdp, cwnd_gain)
+        };
+        self.cwnd = cwnd.max(self.min_cwnd);
+    }
+}
+
+impl CongestionController for BbrLikeController {
+    fn name(&self) -> &'static str {
+        CongestionAlgorithm::BbrLike.as_str()
+    }
+
+    fn on_ack(&mut self, now: Time, rtt_sample: Duration, delivered_bytes: u64) {
+        let rtprop = self.update_rtprop(now, rtt_sample);
+        let window = duration_nanos(rtprop);
+        self.delivered = self.delivered.saturating_add(delivered_bytes);
+        self.delivery_history.push_back((now, self.delivered));
+
+        let new_round = now.duration_since(self.round_start) >= window;
+        if new_round {
+            self.round += 1;
+            self.round_start = now;
+            self.round_delivered = 0;
+            self.round_lost = 0;
+            self.loss_trim = false;
+        }
+        self.round_delivered = self.round_delivered.saturating_add(delivered_bytes);
+        self.sample_delivery_rate(now, window);
+
+        let btlbw = self.btlbw_estimate();
+        if new_round {
+            self.start_round(now, rtt_sample, rtprop, btlbw);
+        }
+        if self.mode == BbrMode::ProbeBw && now.duration_since(self.cycle_stamp) >= window {
+            self.cycle_index = (self.cycle_index + 1) % PROBE_BW_GAINS_PERMILLE.len();
+            self.cycle_stamp = now;
+        }
+        self.update_model(btlbw);
+    }
+
+    fn on_loss(&mut self, _now: Time, loss: LossInfo) {
+        self.round_lost = self.round_lost.saturating_add(loss.lost_bytes);
+        let round_sent = self.round_delivered.saturating_add(self.round_lost);
+        if !self.loss_trim && self.round_lost.saturating_mul(5) > round_sent {
+            self.loss_trim = true;
+            self.update_model(self.btlbw_estimate());
+        }
+    }
+
+    fn pacing_rate(&self) -> u64 {
+        self.pacing_rate
+    }
+
+    fn cwnd(&self) -> u64 {
+        self.cwnd
+    }
+
+    fn phase(&self) -> &'static str {
+        match self.mode {
+            BbrMode::Startup => "startup",
+            BbrMode::Drain => "drain",
+            BbrMode::ProbeBw => "probe_bw",
+        }
+    }
+
+    fn btlbw(&self) -> Option<u64> {
+        Some(self.btlbw_estimate()).filter(|&btlbw| btlbw > 0)
+    }
+
+    fn rtprop(&self) -> Option<Duration> {
+        self.rtprop
+    }
+}
+
+/// Outcome of [`SymbolPacer::poll_release`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum PacerPoll<T> {
+    /// The head symbol was released and now counts as in flight.
+    Ready(T),
+    /// The head symbol's pacing slot opens at this time.
+    Wait(Time),
+    /// The congestion window is full; feedback has to arrive first.
+    WindowFull,
+    /// Nothing is queued.
+    Idle,
+}
+
+/// Send queue accounting of a [`SymbolPacer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacerStats {
+    /// Symbols queued.
+    pub enqueued: u64,
+    /// Symbols released.
+    pub released: u64,
+    /// Bytes released.
+    pub released_bytes: u64,
+    /// Bytes acknowledged by the receiver.
+    pub acked_bytes: u64,
+    /// Bytes the receiver reported lost.
+    pub lost_bytes: u64,
+}
+
+#[derive(Debug)]
+struct PacerGauges {
+    pacing_rate: Arc<GaugeSeries>,
+    cwnd: Arc<GaugeSeries>,
+    btlbw: Arc<GaugeSeries>,
+    rtprop_us: Arc<GaugeSeries>,
+    bytes_in_flight: Arc<GaugeSeries>,
+}
+
+/// Congestion-controlled send queue of one path.
+#[derive(Debug)]
+pub struct SymbolPacer<T> {
+    path: PathId,
+    controller: Box<dyn CongestionController>,
+    queue: VecDeque<(T, u32)>,
+    /// Earliest time the next symbol may be released.
+    next_slot: Time,
+    bytes_in_flight: u64,
+    stats: PacerStats,
+    decisions: u64,
+    event_sample_every: u32,
+    events: VecDeque<CongestionEvent>,
+    gauges: Option<PacerGauges>,
+}
+
+impl<T> SymbolPacer<T> {
+    /// Creates a pacer for `path` driven by `controller`, recording every
+    /// `event_sample_every`-th decision (0 records none).
+    #[must_use]
+    pub fn new(
+        path: PathId,
+        controller: Box<dyn CongestionController>,
+        event_sample_every: u32,
+    ) -> Self {
+        Self {
+            path,
+            controller,
+            queue: VecDeque::new(),
+            next_slot: Time::ZERO,
+            bytes_in_flight: 0,
+            stats: PacerStats::default(),
+            decisions: 0,
+            event_sample_every,
+            events: VecDeque::new(),
+            gauges: None,
+        }
+    }
+
+    /// Returns the path this pacer sends on.
+    #[must_use]
+    pub const fn path(&self) -> PathId {
+        self.path
+    }
+
+    /// Returns the controller.
+    #[must_use]
+    pub fn controller(&self) -> &dyn CongestionController {
+        self.controller.as_ref()
+    }
+
+    /// Returns the controller state.
+    #[must_use]
+    pub fn snapshot(&self) -> CongestionSnapshot {
+        CongestionSnapshot {
+            algorithm: self.controller.name(),
+            phase: self.controller.phase(),
+            pacing_rate: self.controller.pacing_rate(),
+            cwnd: self.controller.cwnd(),
+            btlbw: self.controller.btlbw(),
+            rtprop: self.controller.rtprop(),
+            bytes_in_flight: self.bytes_in_flight,
+        }
+    }
+
+    /// Returns the send queue accounting.
+    #[must_use]
+    pub const fn stats(&self) -> PacerStats {
+        self.stats
+    }
+
+    /// Returns the number of queued symbols.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns the bytes released and not yet acknowledged or lost.
+    #[must_use]
+    pub const fn bytes_in_flight(&self) -> u64 {
+        self.bytes_in_flight
+    }
+
+    /// Queues a symbol of `bytes` bytes behind those already queued.
+    pub fn enqueue(&mut self, symbol: T, bytes: u32) {
+        self.queue.push_back((symbol, bytes));
+        self.stats.enqueued += 1;
+    }
+
+    /// Releases the head symbol if the window and its pacing slot allow.
+    ///
+    /// A symbol is always released when nothing is in flight, so a window
+    /// smaller than one symbol cannot stall the path.
+    pub fn poll_release(&mut self, now: Time) -> PacerPoll<T> {
+        let Some(&(_, bytes)) = self.queue.front() else {
+            return PacerPoll::Idle;
+        };
+        let bytes = u64::from(bytes);
+        if self.bytes_in_flight > 0
+            && self.bytes_in_flight.saturating_add(bytes) > self.controller.cwnd()
+        {
+            return PacerPoll::WindowFull;
+        }
+        if now < self.next_slot {
+            return PacerPoll::Wait(self.next_slot);
+        }
+        let (symbol, _) = self.queue.pop_front().expect("queue head checked above");
+        self.bytes_in_flight = self.bytes_in_flight.saturating_add(bytes);
+        self.stats.released += 1;
+        self.stats.released_bytes = self.stats.released_bytes.saturating_add(bytes);
+        let interval = transmit_nanos(bytes, self.controller.pacing_rate());
+        self.next_slot = self.next_slot.max(now).saturating_add_nanos(interval);
+        self.publish();
+        PacerPoll::Ready(symbol)
+    }
+
+    /// Waits on the runtime clock for the head symbol's pacing slot and
+    /// releases it.
+    ///
+    /// Returns `None` when nothing is queued or the window is full. If the
+    /// wait is cancelled, the symbol stays queued.
+    pub async fn release(&mut self, cx: &Cx) -> Option<T>
+    where
+        T: Send,
+    {
+        loop {
+            match self.poll_release(cx.now()) {
+                PacerPoll::Ready(symbol) => return Some(symbol),
+                PacerPoll::Wait(slot) => crate::time::sleep_until(slot).await,
+                PacerPoll::WindowFull | PacerPoll::Idle => return None,
+            }
+        }
+    }
+
+    /// Feeds an acknowledgement of `delivered_bytes` to the controller.
+    pub fn on_ack(&mut self, now: Time, rtt_sample: Duration, delivered_bytes: u64) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(delivered_bytes);
+        self.stats.acked_bytes = self.stats.acked_bytes.saturating_add(delivered_bytes);
+        self.controller.on_ack(now, rtt_sample, delivered_bytes);
+        self.decided(now, CongestionCause::Ack);
+    }
+
+    /// Feeds a loss report to the controller.
+    pub fn on_loss(&mut self, now: Time, loss: LossInfo) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(loss.lost_bytes);
+        self.stats.lost_bytes = self.stats.lost_bytes.saturating_add(loss.lost_bytes);
+        self.controller.on_loss(now, loss);
+        self.decided(now, CongestionCause::Loss);
+    }
+
+    /// Returns the sampled decisions recorded so far, oldest first.
+    #[must_use]
+    pub fn events(&self) -> impl Iterator<Item = &CongestionEvent> {
+        self.events.iter()
+    }
+
+    /// Drains the recorded decisions.
+    pub fn take_events(&mut self) -> Vec<CongestionEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// Publishes the controller state of this path as gauges labelled with
+    /// the path id, updated on every release and decision.
+    pub fn register_metrics(&mut self, registry: &MetricsRegistry) -> Result<(), RegistryError> {
+        let path = self.path.0.to_string();
+        let gauge = |name: &str, help: &str| {
+            registry
+                .gauge(MetricOpts::new(name, help).labels(&["path"]))?
+                .with_labels(&[path.as_str()])
+        };
+        self.gauges = Some(PacerGauges {
+            pacing_rate: gauge(
+                "transport_pacing_rate_bytes_per_second",
+                "Congestion-controlled pacing rate of the path.",
+            )?,
+            cwnd: gauge(
+                "transport_congestion_window_bytes",
+                "Congestion window of the path.",
+            )?,
+            btlbw: gauge(
+                "transport_btlbw_bytes_per_second",
+                "Bottleneck bandwidth estimate of the path, 0 if not modelled.",
+            )?,
+            rtprop_us: gauge(
+                "transport_rtprop_microseconds",
+                "Propagation delay estimate of the path, 0 if unknown.",
+            )?,
+            bytes_in_flight: gauge(
+                "transport_bytes_in_flight",
+                "Bytes sent on the path and not yet acknowledged or lost.",
+            )?,
+        });
+        self.publish();
+        Ok(())
+    }
+
+    fn decided(&mut self, now: Time, cause: CongestionCause) {
+        self.decisions += 1;
+        self.publish();
+        let every = u64::from(self.event_sample_every);
+        if every == 0 || !self.decisions.is_multiple_of(every) {
+            return;
+        }
+        let event = CongestionEvent {
+            path: self.path,
+            at: now,
+            decision: self.decisions,
+            cause,
+            state: self.snapshot(),
+        };
+        event.emit();
+        if self.events.len() == MAX_RECORDED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    fn publish(&self) {
+        let Some(gauges) = &self.gauges else {
+            return;
+        };
+        let state = self.snapshot();
+        gauges.pacing_rate.set(gauge_value(state.pacing_rate));
+        gauges.cwnd.set(gauge_value(state.cwnd));
+        gauges.btlbw.set(gauge_value(state.btlbw.unwrap_or(0)));
+        let rtprop_us = state.rtprop.map_or(0, |rtprop| gauge_value(rtprop.as_micros()));
+        gauges.rtprop_us.set(rtprop_us);
+        gauges.bytes_in_flight.set(gauge_value(state.bytes_in_flight));
+    }
+}
+
+fn gauge_value(value: impl TryInto<i64>) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Scales `value` by `permille`, saturating.
+fn apply_gain(value: u64, permille: u64) -> u64 {
+    let scaled = u128::from(value) * u128::from(permille) / 1000;
+    u64::try_from(scaled).unwrap_or(u64::MAX)
+}
+
+/// Rate of `bytes` over `nanos`, in bytes per second.
+fn bytes_per_sec(bytes: u64, nanos: u64) -> u64 {
+    let rate = u128::from(bytes) * NANOS_PER_SEC / u128::from(nanos.max(1));
+    u64::try_from(rate).unwrap_or(u64::MAX)
+}
+
+/// Bytes in flight that fill a path of `btlbw` bytes per second and
+/// `rtprop` propagation delay.
+fn bdp_bytes(btlbw: u64, rtprop: Duration) -> u64 {
+    let bdp = u128::from(btlbw) * rtprop.as_nanos() / NANOS_PER_SEC;
+    u64::try_from(bdp).unwrap_or(u64::MAX)
+}
+
+/// Time to send `bytes` at `rate` bytes per second, rounded up.
+fn transmit_nanos(bytes: u64, rate: u64) -> u64 {
+    let nanos = (u128::from(bytes) * NANOS_PER_SEC).div_ceil(u128::from(rate.max(1)));
+    u64::try_from(nanos).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::LabRuntime;
+    use crate::types::Budget;
+    use parking_lot::Mutex;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    const MS: u64 = 1_000_000;
+    const SEC: u64 = 1_000_000_000;
+    const SYMBOL: u32 = 1250;
+    /// 100 Mbps in bytes per second.
+    const LINK_RATE: u64 = 12_500_000;
+    /// One bandwidth-delay product of the 100 Mbps / 20 ms link.
+    const LINK_QUEUE: u64 = 250_000;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    /// Bottleneck link with a drop-tail queue and symmetric propagation
+    /// delay, whose rate follows a script.
+    struct ScriptedLink {
+        rate: u64,
+        one_way: u64,
+        queue_limit: u64,
+        busy_until: u64,
+        /// `(time, new rate)`, in order.
+        steps: VecDeque<(u64, u64)>,
+        /// Window in which every symbol is lost.
+        blackout: Option<(u64, u64)>,
+    }
+
+    impl ScriptedLink {
+        fn new(rate: u64, rtt_ms: u64, queue_limit: u64) -> Self {
+            Self {
+                rate,
+                one_way: rtt_ms * MS / 2,
+                queue_limit,
+                busy_until: 0,
+                steps: VecDeque::new(),
+                blackout: None,
+            }
+        }
+
+        /// Sends `bytes` at `now`; returns when the sender hears back and
+        /// whether the symbol arrived.
+        fn transmit(&mut self, now: u64, bytes: u64) -> (u64, bool) {
+            while self.steps.front().is_some_and(|&(at, _)| at <= now) {
+                self.rate = self.steps.pop_front().expect("step").1;
+            }
+            if self
+                .blackout
+                .is_some_and(|(from, to)| (from..to).contains(&now))
+            {
+                return (now + 2 * self.one_way, false);
+            }
+            let backlog_nanos = self.busy_until.saturating_sub(now);
+            let backlog = backlog_nanos * self.rate / SEC;
+            if backlog.saturating_add(bytes) > self.queue_limit {
+                return (now + 2 * self.one_way + backlog_nanos, false);
+            }
+            let departs = self.busy_until.max(now) + transmit_nanos(bytes, self.rate);
+            self.busy_until = departs;
+            (departs + 2 * self.one_way, true)
+        }
+    }
+
+    /// Drives a pacer against a scripted link in virtual time, keeping its
+    /// queue topped up.
+    struct LinkSim {
+        pacer: SymbolPacer<u64>,
+        link: ScriptedLink,
+        now: u64,
+        /// `(feedback time, sequence, delivered, send time)`.
+        feedback: BinaryHeap<Reverse<(u64, u64, bool, u64)>>,
+        sequence: u64,
+        next_symbol: u64,
+        released: Vec<u64>,
+        /// `(ack time, bytes)`.
+        acks: Vec<(u64, u64)>,
+    }
+
+    impl LinkSim {
+        fn new(pacer: SymbolPacer<u64>, link: ScriptedLink) -> Self {
+            Self {
+                pacer,
+                link,
+                now: 0,
+                feedback: BinaryHeap::new(),
+                sequence: 0,
+                next_symbol: 0,
+                released: Vec::new(),
+                acks: Vec::new(),
+            }
+        }
+
+        fn with_algorithm(algorithm: CongestionAlgorithm, link: ScriptedLink) -> Self {
+            Self::new(config(algorithm).pacer(PathId(0)), link)
+        }
+
+        fn run_until(&mut self, until: u64) {
+            loop {
+                while self.pacer.queued() < 64 {
+                    self.pacer.enqueue(self.next_symbol, SYMBOL);
+                    self.next_symbol += 1;
+                }
+                let slot = match self.pacer.poll_release(Time::from_nanos(self.now)) {
+                    PacerPoll::Ready(symbol) => {
+                        self.released.push(symbol);
+                        let (at, delivered) = self.link.transmit(self.now, u64::from(SYMBOL));
+                        self.feedback
+                            .push(Reverse((at, self.sequence, delivered, self.now)));
+                        self.sequence += 1;
+                        continue;
+                    }
+                    PacerPoll::Wait(slot) => Some(slot.as_nanos()),
+                    PacerPoll::WindowFull | PacerPoll::Idle => None,
+                };
+                let feedback = self.feedback.peek().map(|Reverse((at, ..))| *at);
+                match slot.into_iter().chain(feedback).min() {
+                    Some(next) if next < until => self.now = next,
+                    _ => {
+                        self.now = until;
+                        return;
+                    }
+                }
+                while self
+                    .feedback
+                    .peek()
+                    .is_some_and(|Reverse((at, ..))| *at <= self.now)
+                {
+                    let Reverse((at, _, delivered, sent)) = self.feedback.pop().expect("peeked");
+                    let now = Time::from_nanos(at);
+                    if delivered {
+                        let rtt = Duration::from_nanos(at - sent);
+                        self.pacer.on_ack(now, rtt, u64::from(SYMBOL));
+                        self.acks.push((at, u64::from(SYMBOL)));
+                    } else {
+                        let lost_bytes = u64::from(SYMBOL);
+                        self.pacer.on_loss(now, LossInfo { lost_bytes });
+                    }
+                }
+            }
+        }
+
+        /// Acknowledged bytes per second over `[from, to)`.
+        fn goodput(&self, from: u64, to: u64) -> u64 {
+            let bytes: u64 = self
+                .acks
+                .iter()
+                .filter(|(at, _)| (from..to).contains(at))
+                .map(|(_, bytes)| bytes)
+                .sum();
+            bytes * SEC / (to - from)
+        }
+
+        /// Checks that every queued symbol was released once, in order, or is
+        /// still queued, and that every released byte is accounted for.
+        fn assert_accounting(&self) {
+            let stats = self.pacer.stats();
+            let queued = stats.enqueued - stats.released;
+            crate::assert_with_log!(
+                queued == self.pacer.queued() as u64,
+                "queued symbols",
+                queued,
+                self.pacer.queued()
+            );
+            let in_order = self.released.iter().copied().eq(0..stats.released);
+            crate::assert_with_log!(in_order, "released in order", true, in_order);
+            let settled = stats.acked_bytes + stats.lost_bytes + self.pacer.bytes_in_flight();
+            crate::assert_with_log!(
+                settled == stats.released_bytes,
+                "released bytes",
+                stats.released_bytes,
+                settled
+            );
+        }
+    }
+
+    fn config(algorithm: CongestionAlgorithm) -> CongestionControlConfig {
+        CongestionControlConfig {
+            algorithm,
+            symbol_bytes: SYMBOL,
+            ..CongestionControlConfig::default()
+        }
+    }
+
+    fn percent_of(value: u64, reference: u64) -> u64 {
+        value * 100 / reference
+    }
+
+    #[test]
+    fn both_controllers_converge_to_link_rate() {
+        init_test("both_controllers_converge_to_link_rate");
+        for algorithm in [CongestionAlgorithm::Aimd, CongestionAlgorithm::BbrLike] {
+            let link = || ScriptedLink::new(LINK_RATE, 20, LINK_QUEUE);
+            let mut sim = LinkSim::with_algorithm(algorithm, link());
+            sim.run_until(2 * SEC);
+            let goodput = percent_of(sim.goodput(SEC, 2 * SEC), LINK_RATE);
+            crate::assert_with_log!(goodput >= 95, algorithm.as_str(), ">= 95%", goodput);
+            sim.assert_accounting();
+
+            let mut replay = LinkSim::with_algorithm(algorithm, link());
+            replay.run_until(2 * SEC);
+            let same = sim.pacer.events().eq(replay.pacer.events());
+            crate::assert_with_log!(same, "replay makes the same decisions", true, same);
+        }
+
+        let mut bbr = LinkSim::with_algorithm(
+            CongestionAlgorithm::BbrLike,
+            ScriptedLink::new(LINK_RATE, 20, LINK_QUEUE),
+        );
+        bbr.run_until(2 * SEC);
+        let state = bbr.pacer.snapshot();
+        let btlbw = percent_of(state.btlbw.expect("btlbw"), LINK_RATE);
+        crate::assert_with_log!((95..=105).contains(&btlbw), "btlbw", "~100%", btlbw);
+        let rtprop = state.rtprop.expect("rtprop");
+        let near_base = rtprop >= Duration::from_millis(20) && rtprop < Duration::from_millis(21);
+        crate::assert_with_log!(near_base, "rtprop", "~20ms", rtprop);
+        crate::assert_with_log!(state.phase == "probe_bw", "phase", "probe_bw", state.phase);
+        crate::test_complete!("both_controllers_converge_to_link_rate");
+    }
+
+    #[test]
+    fn controllers_follow_a_step_change_in_bandwidth() {
+        init_test("controllers_follow_a_step_change_in_bandwidth");
+        for algorithm in [CongestionAlgorithm::Aimd, CongestionAlgorithm::BbrLike] {
+            let mut link = ScriptedLink::new(LINK_RATE, 20, LINK_QUEUE);
+            link.steps = VecDeque::from([(2 * SEC, LINK_RATE / 2), (4 * SEC, LINK_RATE)]);
+            let mut sim = LinkSim::with_algorithm(algorithm, link);
+            sim.run_until(2 * SEC);
+            let before = sim.pacer.snapshot();
+
+            sim.run_until(4 * SEC);
+            let halved = percent_of(sim.goodput(3 * SEC, 4 * SEC), LINK_RATE / 2);
+            crate::assert_with_log!(halved >= 95, "goodput after drop", ">= 95%", halved);
+            let after = sim.pacer.snapshot();
+            crate::assert_with_log!(
+                after.cwnd < before.cwnd,
+                "window shrinks",
+                before.cwnd,
+                after.cwnd
+            );
+            if let Some(btlbw) = after.btlbw {
+                let btlbw = percent_of(btlbw, LINK_RATE / 2);
+                crate::assert_with_log!((95..=105).contains(&btlbw), "btlbw", "~100%", btlbw);
+            }
+
+            sim.run_until(6 * SEC);
+            let restored = percent_of(sim.goodput(5 * SEC, 6 * SEC), LINK_RATE);
+            crate::assert_with_log!(restored >= 95, "goodput after rise", ">= 95%", restored);
+            sim.assert_accounting();
+        }
+        crate::test_complete!("controllers_follow_a_step_change_in_bandwidth");
+    }
+
+    #[test]
+    fn loss_burst_halves_aimd_but_not_bbr_like() {
+        init_test("loss_burst_halves_aimd_but_not_bbr_like");
+        let burst = |algorithm| {
+            let mut link = ScriptedLink::new(LINK_RATE, 20, LINK_QUEUE);
+            link.blackout = Some((2 * SEC, 2 * SEC + 10 * MS));
+            let mut sim = LinkSim::with_algorithm(algorithm, link);
+            sim.run_until(2 * SEC);
+            let before = sim.pacer.snapshot();
+            sim.run_until(2 * SEC + 100 * MS);
+            let after = sim.pacer.snapshot();
+            sim.run_until(3 * SEC);
+            sim.assert_accounting();
+            let goodput = sim.goodput(2 * SEC + 50 * MS, 2 * SEC + 550 * MS);
+            (before, after, percent_of(goodput, LINK_RATE))
+        };
+
+        // AIMD reads the burst as congestion: one multiplicative cut, and
+        // additive growth is too slow to refill the link for a while.
+        let (before, after, goodput) = burst(CongestionAlgorithm::Aimd);
+        let cwnd = percent_of(after.cwnd, before.cwnd);
+        crate::assert_with_log!(cwnd <= 55, "aimd window cut", "<= 55%", cwnd);
+        let rate = percent_of(after.pacing_rate, before.pacing_rate);
+        crate::assert_with_log!(rate < 80, "aimd pacing cut", "< 80%", rate);
+        crate::assert_with_log!(goodput < 85, "aimd goodput dips", "< 85%", goodput);
+
+        // The BBR-like model is unchanged by loss that comes with no drop in
+        // delivery rate, so the link stays full.
+        let (before, after, goodput) = burst(CongestionAlgorithm::BbrLike);
+        let btlbw = percent_of(after.btlbw.expect("btlbw"), before.btlbw.expect("btlbw"));
+        crate::assert_with_log!(btlbw >= 95, "bbr_like btlbw kept", ">= 95%", btlbw);
+        let rate = percent_of(after.pacing_rate, before.pacing_rate);
+        crate::assert_with_log!(rate >= 75, "bbr_like pacing kept", ">= 75%", rate);
+        crate::assert_with_log!(goodput >= 95, "bbr_like goodput", ">= 95%", goodput);
+        crate::test_complete!("loss_burst_halves_aimd_but_not_bbr_like");
+    }
+
+    #[test]
+    fn pacer_loses_no_symbols_on_a_lossless_link() {
+        init_test("pacer_loses_no_symbols_on_a_lossless_link");
+        for algorithm in [CongestionAlgorithm::Aimd, CongestionAlgorithm::BbrLike] {
+            let link = ScriptedLink::new(LINK_RATE, 20, u64::MAX);
+            let mut sim = LinkSim::with_algorithm(algorithm, link);
+            sim.run_until(SEC);
+            let lost = sim.pacer.stats().lost_bytes;
+            crate::assert_with_log!(lost == 0, algorithm.as_str(), 0, lost);
+            sim.assert_accounting();
+        }
+
+        // Window- and slot-limited polls hand nothing out and drop nothing.
+        let narrow = CongestionControlConfig {
+            initial_cwnd_symbols: 4,
+            ..config(CongestionAlgorithm::Aimd)
+        };
+        let mut pacer = narrow.pacer::<u64>(PathId(3));
+        for symbol in 0..10 {
+            pacer.enqueue(symbol, SYMBOL);
+        }
+        let mut released = Vec::new();
+        let mut now = Time::ZERO;
+        let mut drain = |pacer: &mut SymbolPacer<u64>, now: &mut Time| loop {
+            match pacer.poll_release(*now) {
+                PacerPoll::Ready(symbol) => released.push(symbol),
+                PacerPoll::Wait(slot) => *now = slot,
+                PacerPoll::WindowFull | PacerPoll::Idle => break,
+            }
+        };
+        drain(&mut pacer, &mut now);
+        let full = pacer.poll_release(now) == PacerPoll::WindowFull;
+        crate::assert_with_log!(full, "window full", true, full);
+        crate::assert_with_log!(pacer.queued() == 6, "still queued", 6, pacer.queued());
+
+        pacer.on_ack(now, Duration::from_millis(20), u64::from(SYMBOL) * 4);
+        drain(&mut pacer, &mut now);
+        let expected: Vec<u64> = (0..10).collect();
+        crate::assert_with_log!(released == expected, "all released", expected, released);
+        crate::test_complete!("pacer_loses_no_symbols_on_a_lossless_link");
+    }
+
+    #[test]
+    fn each_path_converges_with_its_own_controller() {
+        init_test("each_path_converges_with_its_own_controller");
+        let transport = crate::config::TransportConfig {
+            congestion: config(CongestionAlgorithm::BbrLike),
+            ..crate::config::TransportConfig::default()
+        };
+        let links = [
+            (PathId(0), LINK_RATE, 20, LINK_QUEUE),
+            (PathId(1), 2_500_000, 40, 100_000),
+        ];
+        for (path, rate, rtt_ms, queue) in links {
+            let pacer = transport.path_pacer(path);
+            let mut sim = LinkSim::new(pacer, ScriptedLink::new(rate, rtt_ms, queue));
+            sim.run_until(3 * SEC);
+            let btlbw = percent_of(sim.pacer.snapshot().btlbw.expect("btlbw"), rate);
+            crate::assert_with_log!((95..=105).contains(&btlbw), "path btlbw", "~100%", btlbw);
+            let goodput = percent_of(sim.goodput(2 * SEC, 3 * SEC), rate);
+            crate::assert_with_log!(goodput >= 95, "path goodput", ">= 95%", goodput);
+            let own_path = sim.pacer.events().all(|event| event.path == path);
+            crate::assert_with_log!(own_path, "events carry the path", true, own_path);
+        }
+        crate::test_complete!("each_path_converges_with_its_own_controller");
+    }
+
+    #[test]
+    fn decisions_are_sampled_and_published_as_gauges() {
+        init_test("decisions_are_sampled_and_published_as_gauges");
+        let registry = MetricsRegistry::new();
+        let link = ScriptedLink::new(LINK_RATE, 20, LINK_QUEUE);
+        let mut sim = LinkSim::with_algorithm(CongestionAlgorithm::BbrLike, link);
+        sim.pacer
+            .register_metrics(&registry)
+            .expect("register gauges");
+        sim.run_until(SEC);
+
+        let stats = sim.pacer.stats();
+        let decisions = (stats.acked_bytes + stats.lost_bytes) / u64::from(SYMBOL);
+        let events = sim.pacer.take_events();
+        let expected = decisions / 64;
+        crate::assert_with_log!(
+            events.len() as u64 == expected,
+            "sampled events",
+            expected,
+            events.len()
+        );
+        let sampled = events.iter().all(|event| event.decision % 64 == 0);
+        crate::assert_with_log!(sampled, "every 64th decision", true, sampled);
+        let last = events.last().expect("events");
+        crate::assert_with_log!(
+            last.state.algorithm == "bbr_like",
+            "algorithm",
+            "bbr_like",
+            last
+        );
+
+        let state = sim.pacer.snapshot();
+        let gauges = sim.pacer.gauges.as_ref().expect("gauges");
+        let published = [
+            (gauges.pacing_rate.get(), state.pacing_rate as i64),
+            (gauges.cwnd.get(), state.cwnd as i64),
+            (gauges.btlbw.get(), state.btlbw.unwrap_or(0) as i64),
+            (gauges.bytes_in_flight.get(), state.bytes_in_flight as i64),
+            (
+                gauges.rtprop_us.get(),
+                state.rtprop.map_or(0, |rtprop| rtprop.as_micros() as i64),
+            ),
+        ];
+        for (gauge, value) in published {
+            crate::assert_with_log!(gauge == value, "gauge matches state", value, gauge);
+        }
+        let text = registry.render_prometheus();
+        let exposed = text.contains("transport_pacing_rate_bytes_per_second{path=\"0\"}");
+        crate::assert_with_log!(exposed, "exposed", true, exposed);
+        crate::test_complete!("decisions_are_sampled_and_published_as_gauges");
+    }
+
+    #[test]
+    fn release_sleeps_on_the_lab_clock() {
+        init_test("release_sleeps_on_the_lab_clock");
+        let releases = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&releases);
+        let mut runtime = LabRuntime::with_seed(7);
+        let region = runtime.state.create_root_region(Budget::INFINITE);
+        let (task, _handle) = runtime
+            .state
+            .create_task(region, Budget::INFINITE, async move {
+                let cx = Cx::current().expect("lab cx");
+                // 1250-byte symbols at the initial 1.25 MB/s: one per millisecond.
+                let pacing = CongestionControlConfig {
+                    initial_cwnd_symbols: 100,
+                    ..config(CongestionAlgorithm::Aimd)
+                };
+                let mut pacer = pacing.pacer::<u64>(PathId(0));
+                for symbol in 0..20 {
+                    pacer.enqueue(symbol, SYMBOL);
+                }
+                let start = cx.now();
+                while let Some(symbol) = pacer.release(&cx).await {
+                    log.lock().push((symbol, cx.now().duration_since(start)));
+                }
+            })
+            .expect("create pacing task");
+        runtime.scheduler.lock().schedule(task, 0);
+        runtime.run_with_auto_advance();
+
+        let releases = releases.lock().clone();
+        let expected: Vec<(u64, u64)> = (0..20).map(|symbol| (symbol, symbol * MS)).collect();
+        crate::assert_with_log!(releases == expected, "release times", expected, releases);
+        crate::test_complete!("release_sleeps_on_the_lab_clock");
+    }
+
+    #[test]
+    fn config_validation_rejects_inconsistent_settings() {
+        init_test("config_validation_rejects_inconsistent_settings");
+        let valid = CongestionControlConfig::default().validate();
+        crate::assert_with_log!(valid.is_ok(), "default", "Ok", valid);
+        let invalid = [
+            CongestionControlConfig {
+                symbol_bytes: 0,
+                ..CongestionControlConfig::default()
+            },
+            CongestionControlConfig {
+                initial_cwnd_symbols: 2,
+                ..CongestionControlConfig::default()
+            },
+            CongestionControlConfig {
+                initial_pacing_rate: 0,
+                ..CongestionControlConfig::default()
+            },
+            CongestionControlConfig {
+                aimd_decrease_permille: 1000,
+                ..CongestionControlConfig::default()
+            },
+        ];
+        for config in invalid {
+            let result = config.validate();
+            crate::assert_with_log!(result.is_err(), "rejected", "Err", result);
+        }
+        crate::test_complete!("config_validation_rejects_inconsistent_settings");
+    }
+}
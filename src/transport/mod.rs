@@ -4,6 +4,7 @@
 //! across different transport mechanisms (TCP, UDP, in-memory, etc.).
 
 pub mod aggregator;
+pub mod congestion;
 #[cfg(any(test, feature = "test-internals"))]
 #[path = "mo\u{63}k.rs"]
 pub mod deterministic;
@@ -23,6 +24,11 @@ pub use aggregator::{
     ReordererStats, SymbolDeduplicator, SymbolReorderer, TransportCodingPolicy,
     TransportExperimentContext, TransportExperimentDecision, TransportPath,
 };
+pub use congestion::{
+    AimdController, BbrLikeController, CongestionAlgorithm, CongestionCause,
+    CongestionControlConfig, CongestionController, CongestionEvent, CongestionSnapshot, LossInfo,
+    PacerPoll, PacerStats, SymbolPacer,
+};
 #[cfg(any(test, feature = "test-internals"))]
 pub use deterministic::{
     NodeId, SimChannelSink, SimChannelStream, SimLink, SimNetwork, SimSymbolSink, SimSymbolStream,
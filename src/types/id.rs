@@ -2,6 +2,12 @@
 //!
 //! These types provide type-safe identifiers for the core runtime entities:
 //! regions, tasks, and obligations. They wrap arena indices with type safety.
+//!
+//! Each id pairs a slot index with the slot's generation, which the arena
+//! bumps whenever the slot is reused, so an id outliving its entity never
+//! names the slot's next occupant. [`StaleId`] is the typed error for such
+//! ids; [`IdRef`] parses the qualified display form (`T5:2`) and, only when
+//! asked to, the legacy bare form (`T5`).
 
 use crate::util::ArenaIndex;
 use core::fmt;
@@ -149,9 +155,20 @@ impl fmt::Debug for RegionId {
 }
 
 impl fmt::Display for RegionId {
+    /// `R5` by default; the alternate form `{:#}` adds the generation
+    /// (`R5:2`), which is the form [`FromStr`](std::str::FromStr) parses.
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "R{}", self.0.index())
+        fmt_id(f, IdKind::Region, self.0)
+    }
+}
+
+impl std::str::FromStr for RegionId {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IdRef::parse(IdKind::Region, s, IdParseMode::Strict)
+            .map(|id| Self(id.to_arena_strict()))
     }
 }
 
@@ -332,9 +349,20 @@ impl fmt::Debug for TaskId {
 }
 
 impl fmt::Display for TaskId {
+    /// `T5` by default; the alternate form `{:#}` adds the generation
+    /// (`T5:2`), which is the form [`FromStr`](std::str::FromStr) parses.
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "T{}", self.0.index())
+        fmt_id(f, IdKind::Task, self.0)
+    }
+}
+
+impl std::str::FromStr for TaskId {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IdRef::parse(IdKind::Task, s, IdParseMode::Strict)
+            .map(|id| Self(id.to_arena_strict()))
     }
 }
 
@@ -432,9 +460,20 @@ impl fmt::Debug for ObligationId {
 }
 
 impl fmt::Display for ObligationId {
+    /// `O5` by default; the alternate form `{:#}` adds the generation
+    /// (`O5:2`), which is the form [`FromStr`](std::str::FromStr) parses.
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "O{}", self.0.index())
+        fmt_id(f, IdKind::Obligation, self.0)
+    }
+}
+
+impl std::str::FromStr for ObligationId {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IdRef::parse(IdKind::Obligation, s, IdParseMode::Strict)
+            .map(|id| Self(id.to_arena_strict()))
     }
 }
 
@@ -460,6 +499,319 @@ impl<'de> Deserialize<'de> for ObligationId {
     }
 }
 
+// ─── Generation-checked references ─────────────────────────────────────────
+
+/// Which runtime table an identifier indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdKind {
+    /// A [`RegionId`].
+    Region,
+    /// A [`TaskId`].
+    Task,
+    /// An [`ObligationId`].
+    Obligation,
+}
+
+impl IdKind {
+    /// The one-letter prefix used by the display form (`R`, `T`, `O`).
+    #[must_use]
+    pub const fn prefix(self) -> char {
+        match self {
+            Self::Region => 'R',
+            Self::Task => 'T',
+            Self::Obligation => 'O',
+        }
+    }
+
+    /// The serde discriminant tag for this kind.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Region => KIND_REGION_ID,
+            Self::Task => KIND_TASK_ID,
+            Self::Obligation => KIND_OBLIGATION_ID,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            KIND_REGION_ID => Some(Self::Region),
+            KIND_TASK_ID => Some(Self::Task),
+            KIND_OBLIGATION_ID => Some(Self::Obligation),
+            _ => None,
+        }
+    }
+}
+
+#[inline]
+fn fmt_id(f: &mut fmt::Formatter<'_>, kind: IdKind, arena: ArenaIndex) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "{}{}:{}", kind.prefix(), arena.index(), arena.generation())
+    } else {
+        write!(f, "{}{}", kind.prefix(), arena.index())
+    }
+}
+
+/// An identifier whose slot has been freed or reused since it was issued.
+///
+/// Arena slots are recycled with a bumped generation, so an id held past the
+/// lifetime of its entity (a dashboard query, a remote cancel, a monitor
+/// request) still names the slot but no longer the entity. Generation-checked
+/// lookups return this error instead of acting on the slot's new occupant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleId {
+    /// The table the id indexes.
+    pub kind: IdKind,
+    /// The slot index.
+    pub index: u32,
+    /// The generation the caller presented, or `None` for a lenient
+    /// reference that matched nothing.
+    pub generation: Option<u32>,
+    /// The generation now occupying the slot, or `None` if it is vacant.
+    pub current: Option<u32>,
+}
+
+impl StaleId {
+    /// Builds the error for a strict id whose generation did not match.
+    #[must_use]
+    pub const fn new(kind: IdKind, id: ArenaIndex, current: Option<u32>) -> Self {
+        Self {
+            kind,
+            index: id.index(),
+            generation: Some(id.generation()),
+            current,
+        }
+    }
+}
+
+impl fmt::Display for StaleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "stale {} {}{}", self.kind.name(), self.kind.prefix(), self.index)?;
+        if let Some(generation) = self.generation {
+            write!(f, ":{generation}")?;
+        }
+        match self.current {
+            Some(current) => write!(f, " (slot now holds generation {current})"),
+            None => write!(f, " (slot is vacant)"),
+        }
+    }
+}
+
+impl std::error::Error for StaleId {}
+
+/// Whether an identifier without a generation is accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdParseMode {
+    /// Require `T5:2`; a bare `T5` is rejected.
+    #[default]
+    Strict,
+    /// Also accept the legacy bare form `T5`, which then matches whatever
+    /// currently occupies the slot. Only for reading old dumps and logs.
+    Lenient,
+}
+
+/// Error returned when an identifier string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdParseError {
+    /// The input is not of the form `<prefix><index>[:<generation>]`.
+    Malformed {
+        /// The expected kind.
+        kind: IdKind,
+        /// The rejected input.
+        input: String,
+    },
+    /// The input has no generation and parsing was strict.
+    MissingGeneration {
+        /// The expected kind.
+        kind: IdKind,
+        /// The slot index that was parsed.
+        index: u32,
+    },
+}
+
+impl fmt::Display for IdParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { kind, input } => write!(
+                f,
+                "malformed {} {input:?}: expected {}<index>:<generation>",
+                kind.name(),
+                kind.prefix()
+            ),
+            Self::MissingGeneration { kind, index } => write!(
+                f,
+                "{} {}{index} has no generation; lenient parsing is required to accept it",
+                kind.name(),
+                kind.prefix()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IdParseError {}
+
+/// A parsed reference to a region, task, or obligation.
+///
+/// Strictly parsed references always carry a generation. A lenient reference
+/// parsed from the legacy bare form (`T5`) has none and resolves to the
+/// slot's current occupant; it is only produced under
+/// [`IdParseMode::Lenient`] or by deserializing an `IdRef` directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdRef {
+    kind: IdKind,
+    index: u32,
+    generation: Option<u32>,
+}
+
+impl IdRef {
+    /// Parses `<prefix><index>:<generation>`, or `<prefix><index>` when
+    /// `mode` is [`IdParseMode::Lenient`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IdParseError::Malformed`] for a wrong prefix or non-numeric
+    /// parts and [`IdParseError::MissingGeneration`] for a bare index under
+    /// [`IdParseMode::Strict`].
+    pub fn parse(kind: IdKind, input: &str, mode: IdParseMode) -> Result<Self, IdParseError> {
+        let malformed = || IdParseError::Malformed {
+            kind,
+            input: input.to_string(),
+        };
+        let body = input.strip_prefix(kind.prefix()).ok_or_else(malformed)?;
+        let (index, generation) = match body.split_once(':') {
+            Some((index, generation)) => (index, Some(generation)),
+            None => (body, None),
+        };
+        let index = parse_component(index).ok_or_else(malformed)?;
+        let generation = match generation {
+            Some(generation) => Some(parse_component(generation).ok_or_else(malformed)?),
+            None if mode == IdParseMode::Lenient => None,
+            None => return Err(IdParseError::MissingGeneration { kind, index }),
+        };
+        Ok(Self {
+            kind,
+            index,
+            generation,
+        })
+    }
+
+    /// A strict reference to `id`.
+    #[must_use]
+    pub const fn exact(kind: IdKind, id: ArenaIndex) -> Self {
+        Self {
+            kind,
+            index: id.index(),
+            generation: Some(id.generation()),
+        }
+    }
+
+    /// The table this reference indexes.
+    #[must_use]
+    pub const fn kind(&self) -> IdKind {
+        self.kind
+    }
+
+    /// The slot index.
+    #[must_use]
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The generation, or `None` for a lenient reference.
+    #[must_use]
+    pub const fn generation(&self) -> Option<u32> {
+        self.generation
+    }
+
+    /// Resolves this reference against the generation currently occupying
+    /// its slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaleId`] if the slot is vacant or, for a strict reference,
+    /// holds a different generation.
+    pub fn resolve(&self, current: Option<u32>) -> Result<ArenaIndex, StaleId> {
+        match (self.generation, current) {
+            (Some(generation), Some(current)) if generation == current => {
+                Ok(ArenaIndex::new(self.index, generation))
+            }
+            (None, Some(current)) => Ok(ArenaIndex::new(self.index, current)),
+            _ => Err(StaleId {
+                kind: self.kind,
+                index: self.index,
+                generation: self.generation,
+                current,
+            }),
+        }
+    }
+
+    fn to_arena_strict(self) -> ArenaIndex {
+        ArenaIndex::new(self.index, self.generation.unwrap_or_default())
+    }
+}
+
+impl fmt::Display for IdRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.kind.prefix(), self.index)?;
+        match self.generation {
+            Some(generation) => write!(f, ":{generation}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a decimal `u32` without sign or surrounding whitespace.
+fn parse_component(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Wire shape for [`IdRef`]: the id envelope with an optional generation.
+#[derive(Serialize, Deserialize)]
+struct SerdeIdRef {
+    kind: String,
+    index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    generation: Option<u32>,
+}
+
+impl Serialize for IdRef {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerdeIdRef {
+            kind: self.kind.name().to_string(),
+            index: self.index,
+            generation: self.generation,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IdRef {
+    /// Accepts the id envelope with or without `generation`. Deserializing
+    /// into `IdRef` rather than a concrete id type is the lenient opt-in;
+    /// [`RegionId`], [`TaskId`], and [`ObligationId`] reject a missing
+    /// generation.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let wire = SerdeIdRef::deserialize(deserializer)?;
+        let kind = IdKind::from_name(&wire.kind).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown ID kind {:?}", wire.kind))
+        })?;
+        Ok(Self {
+            kind,
+            index: wire.index,
+            generation: wire.generation,
+        })
+    }
+}
+
 /// A logical timestamp for the runtime.
 ///
 /// In the production runtime, this corresponds to wall-clock time.
@@ -1010,4 +1362,76 @@ mod tests {
         assert!(serde_json::from_str::<RegionId>(&obl_json).is_err());
         assert!(serde_json::from_str::<TaskId>(&obl_json).is_err());
     }
+
+    #[test]
+    fn display_format_is_stable() {
+        let region = RegionId::from_arena(ArenaIndex::new(7, 3));
+        let task = TaskId::from_arena(ArenaIndex::new(11, 2));
+        let obl = ObligationId::from_arena(ArenaIndex::new(4, 0));
+
+        // The default form is unchanged so existing logs and snapshots hold.
+        assert_eq!(region.to_string(), "R7");
+        assert_eq!(task.to_string(), "T11");
+        assert_eq!(obl.to_string(), "O4");
+
+        assert_eq!(format!("{region:#}"), "R7:3");
+        assert_eq!(format!("{task:#}"), "T11:2");
+        assert_eq!(format!("{obl:#}"), "O4:0");
+    }
+
+    #[test]
+    fn qualified_display_round_trips_through_from_str() {
+        let task = TaskId::from_arena(ArenaIndex::new(11, 2));
+        assert_eq!(format!("{task:#}").parse::<TaskId>(), Ok(task));
+        let region = RegionId::from_arena(ArenaIndex::new(0, 9));
+        assert_eq!(format!("{region:#}").parse::<RegionId>(), Ok(region));
+
+        assert_eq!(
+            "T11".parse::<TaskId>(),
+            Err(IdParseError::MissingGeneration {
+                kind: IdKind::Task,
+                index: 11
+            })
+        );
+        for bad in ["R11:2", "T", "T:2", "T11:", "T+1:2", "T11:2:3", " T11:2"] {
+            assert!(
+                matches!(bad.parse::<TaskId>(), Err(IdParseError::Malformed { .. })),
+                "{bad:?} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_parsing_matches_current_occupant_only_when_requested() {
+        assert!(IdRef::parse(IdKind::Task, "T5", IdParseMode::Strict).is_err());
+        let any = IdRef::parse(IdKind::Task, "T5", IdParseMode::Lenient).expect("lenient");
+        assert_eq!(any.generation(), None);
+        assert_eq!(any.to_string(), "T5");
+        assert_eq!(any.resolve(Some(4)), Ok(ArenaIndex::new(5, 4)));
+        assert!(any.resolve(None).is_err());
+
+        let exact = IdRef::parse(IdKind::Task, "T5:3", IdParseMode::Lenient).expect("exact");
+        assert_eq!(exact.resolve(Some(3)), Ok(ArenaIndex::new(5, 3)));
+        let stale = exact.resolve(Some(4)).expect_err("generation moved on");
+        assert_eq!(stale.current, Some(4));
+        assert_eq!(stale.to_string(), "stale TaskId T5:3 (slot now holds generation 4)");
+    }
+
+    #[test]
+    fn serde_requires_generation_unless_lenient() {
+        let task = TaskId::from_arena(ArenaIndex::new(11, 2));
+        let json = serde_json::to_string(&task).expect("serialise TaskId");
+        assert_eq!(json, r#"{"kind":"TaskId","index":11,"generation":2}"#);
+
+        let legacy = r#"{"kind":"TaskId","index":11}"#;
+        assert!(serde_json::from_str::<TaskId>(legacy).is_err());
+
+        let lenient: IdRef = serde_json::from_str(legacy).expect("lenient IdRef");
+        assert_eq!(lenient.kind(), IdKind::Task);
+        assert_eq!(lenient.generation(), None);
+        let exact: IdRef = serde_json::from_str(&json).expect("exact IdRef");
+        assert_eq!(exact, IdRef::exact(IdKind::Task, task.arena_index()));
+        assert_eq!(serde_json::to_string(&exact).expect("serialise IdRef"), json);
+        assert!(serde_json::from_str::<IdRef>(r#"{"kind":"Nope","index":1}"#).is_err());
+    }
 }
@@ -37,7 +37,9 @@ pub use cancel::{
     CancelAttributionConfig, CancelKind, CancelPhase, CancelReason, CancelWitness,
     CancelWitnessError,
 };
-pub use id::{ObligationId, RegionId, TaskId, Time};
+pub use id::{
+    IdKind, IdParseError, IdParseMode, IdRef, ObligationId, RegionId, StaleId, TaskId, Time,
+};
 
 // Canonical FrankenSuite identifiers.
 //
@@ -223,6 +223,9 @@ pub enum Outcome<T, E> {
     /// The operation was cancelled.
     Cancelled(CancelReason),
     /// The operation panicked.
+    ///
+    /// Only produced when panics unwind. Under `panic = "abort"` the process
+    /// ends at the panic and this variant never occurs.
     Panicked(PanicPayload),
 }
 
@@ -146,7 +146,7 @@ impl Default for SystemPressure {
 /// should make the runtime *more* cautious, not less. Finite-but-
 /// out-of-range values clamp to `[0.0, 1.0]` as before.
 #[inline]
-fn sanitise_headroom(headroom: f32) -> f32 {
+pub(crate) fn sanitise_headroom(headroom: f32) -> f32 {
     if headroom.is_finite() {
         headroom.clamp(0.0, 1.0)
     } else {
@@ -828,12 +828,18 @@ pub struct CxInner {
     pub task: TaskId,
     /// Optional task type label for adaptive monitoring/metrics.
     pub task_type: Option<String>,
+    /// Call site that spawned this task, when it went through a tracked
+    /// spawn path (see [`crate::trace::symbols`]).
+    pub spawn_site: Option<crate::trace::symbols::SpawnSiteId>,
     /// Current budget.
     pub budget: Budget,
     /// Baseline budget used for checkpoint accounting.
     pub budget_baseline: Budget,
     /// Explicit capability/resource envelope carried by this context.
     pub capability_budget: CapabilityBudget,
+    /// Capability restriction this task inherited from the context that
+    /// spawned it, if that context was attenuated.
+    pub capability_restriction: Option<Arc<crate::cx::CapabilityRestriction>>,
     /// Whether cancellation has been requested.
     pub cancel_requested: bool,
     /// The reason for cancellation, if requested.
@@ -875,6 +881,11 @@ pub struct CxInner {
     pub(crate) cancel_waker_registry_closed: bool,
     /// Current mask depth.
     pub mask_depth: u32,
+    /// Whether exit signals from linked tasks are trapped as messages.
+    pub trap_exits: bool,
+    /// Tightest deadline of the `timeout()` futures currently polling this
+    /// task's code; folded into [`Cx::deadline`](crate::cx::Cx::deadline).
+    pub(crate) timeout_deadline: Option<Time>,
     /// Progress checkpoint state.
     pub checkpoint_state: CheckpointState,
     /// Fast atomic flag for cancellation (avoids RwLock on wake hot path).
@@ -890,6 +901,9 @@ pub struct CxInner {
     /// or when the materialised view is requested. Stored as a plain
     /// `AtomicU64` because [`Time`] is just a `u64` nanos counter.
     pub fast_path_last_checkpoint_ns: std::sync::atomic::AtomicU64,
+    /// Priority donated to this task by waiters on inheriting locks it holds.
+    /// Shared with wakers so a boost is visible without taking this lock.
+    pub(crate) priority_inheritance: Arc<crate::sync::TaskPriorityNode>,
 }
 
 impl CxInner {
@@ -900,9 +914,11 @@ impl CxInner {
             region,
             task,
             task_type: None,
+            spawn_site: None,
             budget,
             budget_baseline: budget,
             capability_budget: CapabilityBudget::UNSPECIFIED,
+            capability_restriction: None,
             cancel_requested: false,
             cancel_reason: None,
             cancel_acknowledged: false,
@@ -917,10 +933,13 @@ impl CxInner {
             next_cancel_waker_token: 0,
             cancel_waker_registry_closed: false,
             mask_depth: 0,
+            trap_exits: false,
+            timeout_deadline: None,
             checkpoint_state: CheckpointState::new(),
             fast_cancel: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
             fast_path_count: std::sync::atomic::AtomicU64::new(0),
             fast_path_last_checkpoint_ns: std::sync::atomic::AtomicU64::new(0),
+            priority_inheritance: Arc::new(crate::sync::TaskPriorityNode::new(task)),
         }
     }
 
@@ -461,6 +461,20 @@ impl<T> Arena<T> {
         self.get(index).is_some()
     }
 
+    /// Returns the generation of the value currently occupying `slot`.
+    ///
+    /// Returns `None` if the slot is vacant or was never allocated. Lookups
+    /// use this to tell a stale index (same slot, older generation) apart
+    /// from one that names the live occupant.
+    #[inline]
+    #[must_use]
+    pub fn occupant_generation(&self, slot: u32) -> Option<u32> {
+        match self.slots.get(slot as usize)? {
+            Slot::Occupied { generation, .. } => Some(*generation),
+            Slot::Vacant { .. } => None,
+        }
+    }
+
     /// Iterates over all occupied slots.
     pub fn iter(&self) -> impl Iterator<Item = (ArenaIndex, &T)> {
         self.slots
@@ -637,6 +651,43 @@ mod tests {
         assert_eq!(arena.get(idx2), Some(&2));
     }
 
+    #[test]
+    fn generation_bumps_on_every_reuse_under_churn() {
+        let mut arena = Arena::new();
+        let anchor = arena.insert(u32::MAX);
+        let mut issued = Vec::new();
+        let mut live = Vec::new();
+
+        for round in 0..200_u32 {
+            for value in 0..4 {
+                live.push(arena.insert(round * 4 + value));
+            }
+            // Free in an order that scrambles the free list.
+            live.rotate_left((round % 4) as usize);
+            for idx in live.drain(..) {
+                assert_eq!(arena.occupant_generation(idx.index()), Some(idx.generation()));
+                assert!(arena.remove(idx).is_some());
+                assert_eq!(arena.occupant_generation(idx.index()), None);
+                issued.push(idx);
+            }
+        }
+
+        // Every (slot, generation) pair is issued once, and each slot's
+        // generations strictly increase in issue order.
+        let mut last_generation = std::collections::BTreeMap::new();
+        for idx in &issued {
+            if let Some(prev) = last_generation.insert(idx.index(), idx.generation()) {
+                assert!(idx.generation() > prev, "{idx:?} reissued at or below {prev}");
+            }
+        }
+        let fresh = arena.insert(0);
+        for idx in &issued {
+            assert_eq!(arena.get(*idx), None, "stale {idx:?} must not resolve");
+        }
+        assert_eq!(arena.occupant_generation(fresh.index()), Some(fresh.generation()));
+        assert_eq!(arena.get(anchor), Some(&u32::MAX));
+    }
+
     #[test]
     fn insert_with_passes_assigned_index() {
         let mut arena = Arena::new();
@@ -1,8 +1,12 @@
-//! Cache-line alignment utilities for hot-path data structures.
+//! Caching utilities: cache-line alignment for hot-path data structures and
+//! a bounded in-memory value cache.
 //!
 //! Provides [`CachePadded<T>`] to prevent false sharing between data accessed
 //! by different threads. On most x86-64 and ARM platforms, a cache line is 64 bytes.
 //!
+//! [`Cache`] is an LRU cache with TTL/TTI expiry on a pluggable clock and
+//! stampede-protected async loading; see its documentation for details.
+//!
 //! # When to Use
 //!
 //! Use `CachePadded` for:
@@ -21,6 +25,10 @@
 //! }
 //! ```
 
+mod lru;
+
+pub use lru::{Cache, CacheBuilder, CacheLoadError, CacheMetrics, EvictionCause};
+
 use core::ops::{Deref, DerefMut};
 
 /// The cache line size in bytes for the target platform.
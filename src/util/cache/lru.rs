@@ -0,0 +1,1184 @@
+//! Bounded in-memory cache with expiry and coalesced loading.
+
+use crate::cx::Cx;
+use crate::observability::metrics::{Histogram, HistogramSnapshot};
+use crate::time::{TimeSource, WallClock};
+use crate::types::{PanicPayload, Time};
+use crate::util::DetHashMap;
+use crate::util::singleflight::{Group, SingleflightError};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the load latency histogram buckets.
+const LOAD_LATENCY_BUCKETS_SECS: [f64; 10] =
+    [0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> u64 + Send + Sync>;
+type Listener<K, V> = Box<dyn Fn(&K, &V, EvictionCause) + Send + Sync>;
+type Evicted<K, V> = Vec<(K, V, EvictionCause)>;
+
+/// Why an entry left the cache, as reported to the eviction listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionCause {
+    /// The entry count or weight bound was exceeded.
+    Capacity,
+    /// The entry outlived its time-to-live or time-to-idle.
+    Expired,
+    /// The entry was removed by `invalidate`, `invalidate_if`, or `clear`.
+    Invalidated,
+    /// A newer value was stored under the same key.
+    Replaced,
+}
+
+/// Error returned by [`Cache::get_or_load`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheLoadError<E> {
+    /// The loader returned an error, either just now or from a cached
+    /// failure when negative caching is enabled.
+    Loader(E),
+    /// The loader panicked.
+    Panicked(PanicPayload),
+}
+
+impl<E: fmt::Display> fmt::Display for CacheLoadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loader(error) => write!(f, "cache loader failed: {error}"),
+            Self::Panicked(payload) => write!(f, "cache loader panicked: {payload}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for CacheLoadError<E> {}
+
+/// Point-in-time view of a cache's counters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheMetrics {
+    /// Lookups answered from the cache, including cached failures.
+    pub hits: u64,
+    /// Lookups that found nothing live.
+    pub misses: u64,
+    /// Loader invocations, including background refreshes.
+    pub loads: u64,
+    /// Loader invocations that returned an error or panicked.
+    pub load_errors: u64,
+    /// Entries removed for capacity or expiry.
+    pub evictions: u64,
+    /// Latency of completed loader invocations, in seconds.
+    pub load_latency: HistogramSnapshot,
+}
+
+enum Slot<V, E> {
+    Value(V),
+    Failed(E),
+}
+
+struct Entry<V, E> {
+    slot: Slot<V, E>,
+    weight: u64,
+    written_at: Time,
+    accessed_at: Time,
+    /// Recency stamp; the key is filed under it in `Store::order`.
+    tick: u64,
+    refreshing: bool,
+}
+
+struct Store<K, V, E> {
+    entries: DetHashMap<K, Entry<V, E>>,
+    /// Keys from least to most recently used.
+    order: BTreeMap<u64, K>,
+    next_tick: u64,
+    weight: u64,
+    /// Bumped by every invalidation so loads started before it are not stored.
+    generation: u64,
+}
+
+impl<K: Eq + Hash + Clone, V, E> Store<K, V, E> {
+    fn stamp(&mut self) -> u64 {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        tick
+    }
+
+    fn insert(&mut self, key: K, mut entry: Entry<V, E>) {
+        entry.tick = self.stamp();
+        self.weight += entry.weight;
+        self.order.insert(entry.tick, key.clone());
+        self.entries.insert(key, entry);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<Entry<V, E>> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.weight -= entry.weight;
+        Some(entry)
+    }
+
+    fn touch(&mut self, key: &K, now: Time) {
+        let tick = self.stamp();
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            entry.accessed_at = now;
+            self.order.insert(tick, key.clone());
+        }
+    }
+
+    fn pop_lru(&mut self) -> Option<(K, Entry<V, E>)> {
+        let (_, key) = self.order.pop_first()?;
+        let entry = self.entries.remove(&key)?;
+        self.weight -= entry.weight;
+        Some((key, entry))
+    }
+}
+
+struct Config<K, V> {
+    max_entries: Option<usize>,
+    max_weight: Option<u64>,
+    weigher: Option<Weigher<K, V>>,
+    time_to_live: Option<Duration>,
+    time_to_idle: Option<Duration>,
+    refresh_ahead: Option<Duration>,
+    negative_ttl: Option<Duration>,
+    listener: Option<Listener<K, V>>,
+    clock: Arc<dyn TimeSource>,
+}
+
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    loads: AtomicU64,
+    load_errors: AtomicU64,
+    evictions: AtomicU64,
+    load_latency: Histogram,
+}
+
+enum Lookup<V, E> {
+    Hit { value: V, refresh: bool },
+    Failed(E),
+    Miss,
+}
+
+struct Inner<K, V, E> {
+    config: Config<K, V>,
+    store: Mutex<Store<K, V, E>>,
+    loads: Group<K, Result<V, E>>,
+    counters: Counters,
+}
+
+/// Builder for a [`Cache`].
+///
+/// Every bound is optional; a cache built with no options never evicts and
+/// never expires.
+pub struct CacheBuilder<K, V, E = Infallible> {
+    config: Config<K, V>,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<K, V, E> fmt::Debug for CacheBuilder<K, V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheBuilder")
+            .field("max_entries", &self.config.max_entries)
+            .field("max_weight", &self.config.max_weight)
+            .field("time_to_live", &self.config.time_to_live)
+            .field("time_to_idle", &self.config.time_to_idle)
+            .field("refresh_ahead", &self.config.refresh_ahead)
+            .field("negative_ttl", &self.config.negative_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V, E> CacheBuilder<K, V, E>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    fn new() -> Self {
+        Self {
+            config: Config {
+                max_entries: None,
+                max_weight: None,
+                weigher: None,
+                time_to_live: None,
+                time_to_idle: None,
+                refresh_ahead: None,
+                negative_ttl: None,
+                listener: None,
+                clock: Arc::new(WallClock::new()),
+            },
+            _error: PhantomData,
+        }
+    }
+
+    /// Evicts the least recently used entry once more than `max` are held.
+    #[must_use]
+    pub fn max_entries(mut self, max: usize) -> Self {
+        self.config.max_entries = Some(max);
+        self
+    }
+
+    /// Bounds the total weight of stored values, as measured by `weigher`.
+    ///
+    /// A value heavier than `max` on its own is never stored.
+    #[must_use]
+    pub fn max_weight<W>(mut self, max: u64, weigher: W) -> Self
+    where
+        W: Fn(&K, &V) -> u64 + Send + Sync + 'static,
+    {
+        self.config.max_weight = Some(max);
+        self.config.weigher = Some(Box::new(weigher));
+        self
+    }
+
+    /// Expires entries `ttl` after they were written.
+    #[must_use]
+    pub fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.config.time_to_live = Some(ttl);
+        self
+    }
+
+    /// Expires entries `tti` after they were last read or written.
+    #[must_use]
+    pub fn time_to_idle(mut self, tti: Duration) -> Self {
+        self.config.time_to_idle = Some(tti);
+        self
+    }
+
+    /// Reloads an entry in the background when it is read within `window`
+    /// of its time-to-live.
+    ///
+    /// The stale value keeps being served until the reload lands. Has no
+    /// effect without [`time_to_live`](Self::time_to_live).
+    #[must_use]
+    pub fn refresh_ahead(mut self, window: Duration) -> Self {
+        self.config.refresh_ahead = Some(window);
+        self
+    }
+
+    /// Caches loader failures for `ttl`, returning them without reloading.
+    ///
+    /// Without this, a failed load stores nothing and the next caller retries.
+    #[must_use]
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.config.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Calls `listener` for every value that leaves the cache.
+    ///
+    /// The listener runs after the cache lock is released, so it may call
+    /// back into the cache.
+    #[must_use]
+    pub fn eviction_listener<L>(mut self, listener: L) -> Self
+    where
+        L: Fn(&K, &V, EvictionCause) + Send + Sync + 'static,
+    {
+        self.config.listener = Some(Box::new(listener));
+        self
+    }
+
+    /// Evaluates expiry against `clock` instead of the wall clock.
+    #[must_use]
+    pub fn clock(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
+    /// Builds the cache.
+    #[must_use]
+    pub fn build(self) -> Cache<K, V, E> {
+        Cache {
+            inner: Arc::new(Inner {
+                config: self.config,
+                store: Mutex::new(Store {
+                    entries: DetHashMap::default(),
+                    order: BTreeMap::new(),
+                    next_tick: 0,
+                    weight: 0,
+                    generation: 0,
+                }),
+                loads: Group::new(),
+                counters: Counters {
+                    hits: AtomicU64::new(0),
+                    misses: AtomicU64::new(0),
+                    loads: AtomicU64::new(0),
+                    load_errors: AtomicU64::new(0),
+                    evictions: AtomicU64::new(0),
+                    load_latency: Histogram::new(
+                        "cache_load_latency_seconds",
+                        LOAD_LATENCY_BUCKETS_SECS.to_vec(),
+                    ),
+                },
+            }),
+        }
+    }
+}
+
+/// A bounded in-memory cache with LRU eviction and time-based expiry.
+///
+/// Entries are bounded by count and/or total weight and expire on a
+/// time-to-live and/or time-to-idle measured on the configured
+/// [`TimeSource`], so expiry is exact under a virtual clock. Expired entries
+/// are dropped lazily when looked up, or eagerly by
+/// [`purge_expired`](Self::purge_expired).
+///
+/// [`get_or_load`](Self::get_or_load) coalesces concurrent misses for the same
+/// key through a [`singleflight::Group`](crate::util::singleflight::Group): a
+/// miss storm runs the loader once and every caller receives its result.
+///
+/// The handle is cheap to clone; clones share the same entries.
+///
+/// # Example
+///
+/// ```ignore
+/// use asupersync::util::cache::Cache;
+///
+/// let users = Cache::<UserId, Arc<User>, DbError>::builder()
+///     .max_entries(10_000)
+///     .time_to_live(Duration::from_secs(60))
+///     .refresh_ahead(Duration::from_secs(10))
+///     .build();
+/// let user = users.get_or_load(cx, id, move || fetch_user(db, id)).await?;
+/// ```
+pub struct Cache<K, V, E = Infallible> {
+    inner: Arc<Inner<K, V, E>>,
+}
+
+impl<K, V, E> Clone for Cache<K, V, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V, E> fmt::Debug for Cache<K, V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let store = self.inner.store.lock();
+        f.debug_struct("Cache")
+            .field("entries", &store.entries.len())
+            .field("weight", &store.weight)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V, E> Cache<K, V, E>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    /// Returns a builder for a new cache.
+    #[must_use]
+    pub fn builder() -> CacheBuilder<K, V, E> {
+        CacheBuilder::new()
+    }
+
+    /// Returns the live value for `key`, if any.
+    ///
+    /// A cached failure reads as absent.
+    pub fn get(&self, key: &K) -> Option<V> {
+        match self.inner.lookup(key, false) {
+            Lookup::Hit { value, .. } => {
+                self.inner.counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(value)
+            }
+            Lookup::Failed(_) | Lookup::Miss => {
+                self.inner.counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Returns the value for `key`, running `loader` on a miss.
+    ///
+    /// Concurrent misses for the same key share one loader invocation. When
+    /// refresh-ahead is configured and the hit is close to expiry, `loader`
+    /// is instead spawned in `cx`'s region to replace the entry in the
+    /// background, and the current value is returned immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CacheLoadError::Loader`] if the loader failed (or a cached
+    /// failure is still live) and [`CacheLoadError::Panicked`] if it panicked.
+    pub async fn get_or_load<F, Fut>(
+        &self,
+        cx: &Cx,
+        key: K,
+        loader: F,
+    ) -> Result<V, CacheLoadError<E>>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<V, E>> + Send + 'static,
+    {
+        let generation = match self.inner.lookup(&key, true) {
+            Lookup::Hit { value, refresh } => {
+                self.inner.counters.hits.fetch_add(1, Ordering::Relaxed);
+                if refresh {
+                    self.spawn_refresh(cx, key, loader);
+                }
+                return Ok(value);
+            }
+            Lookup::Failed(error) => {
+                self.inner.counters.hits.fetch_add(1, Ordering::Relaxed);
+                return Err(CacheLoadError::Loader(error));
+            }
+            Lookup::Miss => {
+                self.inner.counters.misses.fetch_add(1, Ordering::Relaxed);
+                self.inner.store.lock().generation
+            }
+        };
+
+        let inner = Arc::clone(&self.inner);
+        let load_key = key.clone();
+        let loaded = self
+            .inner
+            .loads
+            .run(key, move || inner.load(load_key, generation, false, loader))
+            .await;
+        match loaded {
+            Ok(result) => result.map_err(CacheLoadError::Loader),
+            Err(SingleflightError::Panicked(payload)) => Err(CacheLoadError::Panicked(payload)),
+        }
+    }
+
+    /// Stores `value` under `key`, replacing any existing entry.
+    pub fn insert(&self, key: K, value: V) {
+        let now = self.inner.config.clock.now();
+        let mut evicted = Vec::new();
+        {
+            let mut store = self.inner.store.lock();
+            self.inner.put(&mut store, key, Slot::Value(value), now, &mut evicted);
+        }
+        self.inner.notify(evicted);
+    }
+
+    /// Removes the entry for `key`, returning whether one was present.
+    pub fn invalidate(&self, key: &K) -> bool {
+        let mut evicted = Vec::new();
+        let removed = {
+            let mut store = self.inner.store.lock();
+            store.generation += 1;
+            store.remove(key)
+        };
+        let present = removed.is_some();
+        if let Some(Entry {
+            slot: Slot::Value(value),
+            ..
+        }) = removed
+        {
+            evicted.push((key.clone(), value, EvictionCause::Invalidated));
+        }
+        self.inner.notify(evicted);
+        present
+    }
+
+    /// Removes every value for which `predicate` returns `true`, returning
+    /// how many were removed.
+    pub fn invalidate_if<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(&K, &V) -> bool,
+    {
+        let mut evicted = Vec::new();
+        {
+            let mut store = self.inner.store.lock();
+            store.generation += 1;
+            let doomed: Vec<K> = store
+                .entries
+                .iter()
+                .filter(|(key, entry)| match &entry.slot {
+                    Slot::Value(value) => predicate(key, value),
+                    Slot::Failed(_) => false,
+                })
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in doomed {
+                if let Some(Entry {
+                    slot: Slot::Value(value),
+                    ..
+                }) = store.remove(&key)
+                {
+                    evicted.push((key, value, EvictionCause::Invalidated));
+                }
+            }
+        }
+        let count = evicted.len();
+        self.inner.notify(evicted);
+        count
+    }
+
+    /// Removes every entry.
+    pub fn clear(&self) {
+        let mut evicted = Vec::new();
+        {
+            let mut store = self.inner.store.lock();
+            store.generation += 1;
+            while let Some((key, entry)) = store.pop_lru() {
+                if let Slot::Value(value) = entry.slot {
+                    evicted.push((key, value, EvictionCause::Invalidated));
+                }
+            }
+        }
+        self.inner.notify(evicted);
+    }
+
+    /// Drops every expired entry now rather than on its next lookup,
+    /// returning how many were dropped.
+    pub fn purge_expired(&self) -> usize {
+        let now = self.inner.config.clock.now();
+        let mut evicted = Vec::new();
+        let purged = {
+            let mut store = self.inner.store.lock();
+            let expired: Vec<K> = store
+                .entries
+                .iter()
+                .filter(|(_, entry)| self.inner.is_expired(entry, now))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in &expired {
+                self.inner.expire(&mut store, key, &mut evicted);
+            }
+            expired.len()
+        };
+        self.inner.notify(evicted);
+        purged
+    }
+
+    /// Number of entries held, including cached failures and expired entries
+    /// not yet purged.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.store.lock().entries.len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total weight of the stored values.
+    #[must_use]
+    pub fn weighted_size(&self) -> u64 {
+        self.inner.store.lock().weight
+    }
+
+    /// Returns a snapshot of the cache's counters.
+    #[must_use]
+    pub fn metrics(&self) -> CacheMetrics {
+        let counters = &self.inner.counters;
+        CacheMetrics {
+            hits: counters.hits.load(Ordering::Relaxed),
+            misses: counters.misses.load(Ordering::Relaxed),
+            loads: counters.loads.load(Ordering::Relaxed),
+            load_errors: counters.load_errors.load(Ordering::Relaxed),
+            evictions: counters.evictions.load(Ordering::Relaxed),
+            load_latency: counters.load_latency.snapshot(),
+        }
+    }
+
+    fn spawn_refresh<F, Fut>(&self, cx: &Cx, key: K, loader: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<V, E>> + Send + 'static,
+    {
+        let generation = self.inner.store.lock().generation;
+        let inner = Arc::clone(&self.inner);
+        let refresh_key = key.clone();
+        let spawned = cx.spawn(move |_| async move {
+            let loader_inner = Arc::clone(&inner);
+            let load_key = refresh_key.clone();
+            let load = move || loader_inner.load(load_key, generation, true, loader);
+            // Failures are counted by `load` and leave the stale entry in place.
+            let _ = inner.loads.run(refresh_key, load).await;
+        });
+        if spawned.is_err() {
+            self.inner.clear_refreshing(&key);
+        }
+    }
+}
+
+impl<K, V, E> Inner<K, V, E>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    /// Looks `key` up, dropping it if expired and refreshing its recency if
+    /// live. With `may_refresh`, a hit inside the refresh window is claimed
+    /// for a background reload.
+    fn lookup(&self, key: &K, may_refresh: bool) -> Lookup<V, E> {
+        let now = self.config.clock.now();
+        let mut evicted = Vec::new();
+        let lookup = {
+            let mut store = self.store.lock();
+            let expired = store.entries.get(key).map(|entry| self.is_expired(entry, now));
+            match expired {
+                None => Lookup::Miss,
+                Some(true) => {
+                    self.expire(&mut store, key, &mut evicted);
+                    Lookup::Miss
+                }
+                Some(false) => {
+                    store.touch(key, now);
+                    let entry = store.entries.get_mut(key).expect("entry just touched");
+                    match &entry.slot {
+                        Slot::Failed(error) => Lookup::Failed(error.clone()),
+                        Slot::Value(value) => {
+                            let refresh = may_refresh && self.refresh_due(entry, now);
+                            if refresh {
+                                entry.refreshing = true;
+                            }
+                            Lookup::Hit {
+                                value: value.clone(),
+                                refresh,
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        self.notify(evicted);
+        lookup
+    }
+
+    /// Runs `loader` as the leader of a coalesced load and stores its result.
+    async fn load<F, Fut>(
+        self: Arc<Self>,
+        key: K,
+        generation: u64,
+        refresh: bool,
+        loader: F,
+    ) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        self.counters.loads.fetch_add(1, Ordering::Relaxed);
+        let started = self.config.clock.now();
+        let mut guard = LoadGuard {
+            inner: &self,
+            key: &key,
+            refresh,
+            armed: true,
+        };
+        let result = loader().await;
+        guard.armed = false;
+
+        let now = self.config.clock.now();
+        #[allow(clippy::cast_precision_loss)]
+        let elapsed = now.duration_since(started) as f64 / 1_000_000_000.0;
+        self.counters.load_latency.observe(elapsed);
+        if result.is_err() {
+            self.counters.load_errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut evicted = Vec::new();
+        {
+            let mut store = self.store.lock();
+            // An invalidation raced the load; its result may already be stale.
+            let current = store.generation == generation;
+            match &result {
+                Ok(value) if current => {
+                    let slot = Slot::Value(value.clone());
+                    self.put(&mut store, key.clone(), slot, now, &mut evicted);
+                }
+                Err(error) if current && !refresh && self.config.negative_ttl.is_some() => {
+                    let slot = Slot::Failed(error.clone());
+                    self.put(&mut store, key.clone(), slot, now, &mut evicted);
+                }
+                _ => {
+                    if let Some(entry) = store.entries.get_mut(&key) {
+                        entry.refreshing = false;
+                    }
+                }
+            }
+        }
+        self.notify(evicted);
+        result
+    }
+
+    fn put(
+        &self,
+        store: &mut Store<K, V, E>,
+        key: K,
+        slot: Slot<V, E>,
+        now: Time,
+        evicted: &mut Evicted<K, V>,
+    ) {
+        let weight = match (&slot, &self.config.weigher) {
+            (Slot::Value(value), Some(weigher)) => weigher(&key, value),
+            (Slot::Value(_), None) => 1,
+            (Slot::Failed(_), _) => 0,
+        };
+        if let Some(Entry {
+            slot: Slot::Value(old),
+            ..
+        }) = store.remove(&key)
+        {
+            evicted.push((key.clone(), old, EvictionCause::Replaced));
+        }
+        if self.config.max_weight.is_some_and(|max| weight > max) {
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            if let Slot::Value(value) = slot {
+                evicted.push((key, value, EvictionCause::Capacity));
+            }
+            return;
+        }
+
+        store.insert(
+            key,
+            Entry {
+                slot,
+                weight,
+                written_at: now,
+                accessed_at: now,
+                tick: 0,
+                refreshing: false,
+            },
+        );
+        while self.over_capacity(store) {
+            let Some((key, entry)) = store.pop_lru() else {
+                break;
+            };
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            if let Slot::Value(value) = entry.slot {
+                evicted.push((key, value, EvictionCause::Capacity));
+            }
+        }
+    }
+
+    fn over_capacity(&self, store: &Store<K, V, E>) -> bool {
+        self.config
+            .max_entries
+            .is_some_and(|max| store.entries.len() > max)
+            || self.config.max_weight.is_some_and(|max| store.weight > max)
+    }
+
+    fn expire(&self, store: &mut Store<K, V, E>, key: &K, evicted: &mut Evicted<K, V>) {
+        if let Some(entry) = store.remove(key) {
+            self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            if let Slot::Value(value) = entry.slot {
+                evicted.push((key.clone(), value, EvictionCause::Expired));
+            }
+        }
+    }
+
+    /// An entry is live strictly before its deadline and expired from it on.
+    fn is_expired(&self, entry: &Entry<V, E>, now: Time) -> bool {
+        let deadline = match entry.slot {
+            Slot::Failed(_) => self
+                .config
+                .negative_ttl
+                .map(|ttl| entry.written_at.saturating_add_nanos(duration_nanos(ttl))),
+            Slot::Value(_) => {
+                let ttl = self
+                    .config
+                    .time_to_live
+                    .map(|ttl| entry.written_at.saturating_add_nanos(duration_nanos(ttl)));
+                let tti = self
+                    .config
+                    .time_to_idle
+                    .map(|tti| entry.accessed_at.saturating_add_nanos(duration_nanos(tti)));
+                ttl.into_iter().chain(tti).min()
+            }
+        };
+        deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    fn refresh_due(&self, entry: &Entry<V, E>, now: Time) -> bool {
+        let (Some(ttl), Some(window)) = (self.config.time_to_live, self.config.refresh_ahead)
+        else {
+            return false;
+        };
+        let expires_at = entry.written_at.saturating_add_nanos(duration_nanos(ttl));
+        !entry.refreshing && now >= expires_at.saturating_sub_nanos(duration_nanos(window))
+    }
+
+    fn clear_refreshing(&self, key: &K) {
+        if let Some(entry) = self.store.lock().entries.get_mut(key) {
+            entry.refreshing = false;
+        }
+    }
+
+    fn notify(&self, evicted: Evicted<K, V>) {
+        if let Some(listener) = &self.config.listener {
+            for (key, value, cause) in evicted {
+                listener(&key, &value, cause);
+            }
+        }
+    }
+}
+
+/// Accounts for a load abandoned by panic or cancellation.
+struct LoadGuard<'a, K, V, E>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    inner: &'a Inner<K, V, E>,
+    key: &'a K,
+    refresh: bool,
+    armed: bool,
+}
+
+impl<K, V, E> Drop for LoadGuard<'_, K, V, E>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        if std::thread::panicking() {
+            self.inner.counters.load_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.refresh {
+            self.inner.clear_refreshing(self.key);
+        }
+    }
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::run_async_under_lab;
+    use crate::runtime::yield_now;
+    use crate::time::VirtualClock;
+    use std::sync::atomic::AtomicUsize;
+
+    const MS: u64 = 1_000_000;
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    async fn yield_times(times: usize) {
+        for _ in 0..times {
+            yield_now().await;
+        }
+    }
+
+    type Log = Arc<Mutex<Vec<(&'static str, u32, EvictionCause)>>>;
+
+    fn recording(log: &Log) -> impl Fn(&&'static str, &u32, EvictionCause) + Send + Sync {
+        let log = Arc::clone(log);
+        move |key, value, cause| log.lock().push((*key, *value, cause))
+    }
+
+    #[test]
+    fn ttl_and_tti_expire_at_exact_boundaries() {
+        init_test("ttl_and_tti_expire_at_exact_boundaries");
+        let clock = Arc::new(VirtualClock::new());
+        let ttl = Cache::<&str, u32>::builder()
+            .time_to_live(Duration::from_millis(100))
+            .clock(clock.clone())
+            .build();
+        ttl.insert("a", 1);
+        clock.advance(100 * MS - 1);
+        assert_eq!(ttl.get(&"a"), Some(1), "live one nanosecond before ttl");
+        clock.advance(1);
+        assert_eq!(ttl.get(&"a"), None, "expired exactly at ttl");
+
+        let tti = Cache::<&str, u32>::builder()
+            .time_to_idle(Duration::from_millis(50))
+            .clock(clock.clone())
+            .build();
+        tti.insert("a", 1);
+        for _ in 0..4 {
+            clock.advance(50 * MS - 1);
+            assert_eq!(tti.get(&"a"), Some(1), "each read renews idle time");
+        }
+        clock.advance(50 * MS);
+        assert_eq!(tti.get(&"a"), None, "expired after a full idle period");
+
+        let metrics = tti.metrics();
+        crate::assert_with_log!(
+            metrics.hits == 4 && metrics.misses == 1 && metrics.evictions == 1,
+            "tti counters",
+            (4, 1, 1),
+            (metrics.hits, metrics.misses, metrics.evictions)
+        );
+        crate::test_complete!("ttl_and_tti_expire_at_exact_boundaries");
+    }
+
+    #[test]
+    fn weight_bound_evicts_least_recently_used_first() {
+        init_test("weight_bound_evicts_least_recently_used_first");
+        let log = Log::default();
+        let cache = Cache::<&str, u32>::builder()
+            .max_weight(10, |_, value| u64::from(*value))
+            .eviction_listener(recording(&log))
+            .build();
+        cache.insert("a", 3);
+        cache.insert("b", 3);
+        cache.insert("c", 3);
+        assert_eq!(cache.get(&"a"), Some(3), "touch a so b is least recent");
+        cache.insert("d", 5);
+        cache.insert("huge", 11);
+
+        let evicted = log.lock().clone();
+        crate::assert_with_log!(
+            evicted
+                == [
+                    ("b", 3, EvictionCause::Capacity),
+                    ("c", 3, EvictionCause::Capacity),
+                    ("huge", 11, EvictionCause::Capacity),
+                ],
+            "lru order, oversized value rejected",
+            "b, c, huge",
+            evicted
+        );
+        assert_eq!(cache.weighted_size(), 8);
+        assert_eq!(cache.get(&"a"), Some(3));
+        assert_eq!(cache.get(&"d"), Some(5));
+        crate::test_complete!("weight_bound_evicts_least_recently_used_first");
+    }
+
+    #[test]
+    fn miss_storm_runs_one_loader() {
+        init_test("miss_storm_runs_one_loader");
+        for seed in 0..6 {
+            let ((calls, results, metrics), report) = run_async_under_lab(seed, |cx| async move {
+                let cache = Cache::<&str, u32>::builder().build();
+                let calls = Arc::new(AtomicUsize::new(0));
+                let mut handles = Vec::new();
+                for _ in 0..16 {
+                    let cache = cache.clone();
+                    let calls = Arc::clone(&calls);
+                    let handle = cx
+                        .spawn(move |cx| async move {
+                            let loader = move || async move {
+                                calls.fetch_add(1, Ordering::SeqCst);
+                                yield_times(4).await;
+                                Ok(7)
+                            };
+                            cache.get_or_load(&cx, "config", loader).await
+                        })
+                        .expect("spawn caller");
+                    handles.push(handle);
+                }
+                let mut results = Vec::new();
+                for mut handle in handles {
+                    results.push(handle.join(&cx).await.expect("caller finished"));
+                }
+                (calls.load(Ordering::SeqCst), results, cache.metrics())
+            });
+
+            assert!(report.quiescent);
+            crate::assert_with_log!(calls == 1, "one loader call", 1, calls);
+            assert!(results.iter().all(|result| *result == Ok(7)));
+            assert_eq!((metrics.misses, metrics.loads), (16, 1));
+            assert_eq!(metrics.load_latency.count, 1);
+        }
+        crate::test_complete!("miss_storm_runs_one_loader");
+    }
+
+    #[test]
+    fn refresh_ahead_reloads_in_background_before_expiry() {
+        init_test("refresh_ahead_reloads_in_background_before_expiry");
+        let ((served, loads), _) = run_async_under_lab(5, |cx| async move {
+            let clock = Arc::new(VirtualClock::new());
+            let cache = Cache::<&str, u32>::builder()
+                .time_to_live(Duration::from_millis(100))
+                .refresh_ahead(Duration::from_millis(20))
+                .clock(clock.clone())
+                .build();
+            let version = Arc::new(AtomicUsize::new(0));
+            let load = |version: &Arc<AtomicUsize>| {
+                let version = Arc::clone(version);
+                move || async move { Ok(version.fetch_add(1, Ordering::SeqCst) as u32 + 1) }
+            };
+
+            let mut served = Vec::new();
+            served.push(cache.get_or_load(&cx, "k", load(&version)).await);
+            clock.advance(80 * MS - 1);
+            served.push(cache.get_or_load(&cx, "k", load(&version)).await);
+            yield_times(8).await;
+            let before_window = version.load(Ordering::SeqCst);
+
+            clock.advance(1);
+            served.push(cache.get_or_load(&cx, "k", load(&version)).await);
+            // A second hit while the refresh is in flight does not start another.
+            served.push(cache.get_or_load(&cx, "k", load(&version)).await);
+            yield_times(8).await;
+
+            // The refreshed entry has a fresh ttl, so it outlives the original.
+            clock.advance(50 * MS);
+            served.push(cache.get_or_load(&cx, "k", load(&version)).await);
+            (served, (before_window, cache.metrics().loads))
+        });
+
+        crate::assert_with_log!(
+            served == [Ok(1), Ok(1), Ok(1), Ok(1), Ok(2)],
+            "stale value served until refresh lands",
+            "1, 1, 1, 1, 2",
+            served
+        );
+        assert_eq!(loads, (1, 2), "no refresh before the window, one inside it");
+        crate::test_complete!("refresh_ahead_reloads_in_background_before_expiry");
+    }
+
+    #[test]
+    fn failures_are_cached_only_when_opted_in() {
+        init_test("failures_are_cached_only_when_opted_in");
+        let ((plain, negative), _) = run_async_under_lab(9, |cx| async move {
+            let clock = Arc::new(VirtualClock::new());
+            let attempts = Arc::new(AtomicUsize::new(0));
+            let failing = |attempts: &Arc<AtomicUsize>| {
+                let attempts = Arc::clone(attempts);
+                move || async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err::<u32, &str>("unavailable")
+                }
+            };
+
+            let plain = Cache::<&str, u32, &str>::builder().build();
+            for _ in 0..3 {
+                let result = plain.get_or_load(&cx, "k", failing(&attempts)).await;
+                assert_eq!(result, Err(CacheLoadError::Loader("unavailable")));
+            }
+            assert!(plain.is_empty());
+            let plain_attempts = attempts.swap(0, Ordering::SeqCst);
+
+            let negative = Cache::<&str, u32, &str>::builder()
+                .negative_ttl(Duration::from_millis(10))
+                .clock(clock.clone())
+                .build();
+            for _ in 0..3 {
+                let result = negative.get_or_load(&cx, "k", failing(&attempts)).await;
+                assert_eq!(result, Err(CacheLoadError::Loader("unavailable")));
+            }
+            assert_eq!(negative.get(&"k"), None, "a cached failure reads as absent");
+            clock.advance(10 * MS);
+            let recovered = negative
+                .get_or_load(&cx, "k", || async { Ok::<u32, &str>(5) })
+                .await;
+            assert_eq!(recovered, Ok(5));
+            let metrics = negative.metrics();
+            assert_eq!((metrics.loads, metrics.load_errors), (2, 1));
+            (plain_attempts, attempts.load(Ordering::SeqCst))
+        });
+
+        crate::assert_with_log!(plain == 3, "failures retried by default", 3, plain);
+        crate::assert_with_log!(negative == 1, "failure cached for its ttl", 1, negative);
+        crate::test_complete!("failures_are_cached_only_when_opted_in");
+    }
+
+    #[test]
+    fn eviction_listener_sees_every_cause() {
+        init_test("eviction_listener_sees_every_cause");
+        let clock = Arc::new(VirtualClock::new());
+        let log = Log::default();
+        let cache = Cache::<&str, u32>::builder()
+            .max_entries(3)
+            .time_to_live(Duration::from_millis(10))
+            .eviction_listener(recording(&log))
+            .clock(clock.clone())
+            .build();
+        cache.insert("a", 1);
+        cache.insert("a", 2);
+        cache.insert("b", 3);
+        cache.insert("c", 4);
+        cache.insert("d", 5);
+        assert!(cache.invalidate(&"b"));
+        assert!(!cache.invalidate(&"b"));
+        assert_eq!(cache.invalidate_if(|_, value| *value == 4), 1);
+        cache.insert("e", 6);
+        clock.advance(10 * MS);
+        assert_eq!(cache.purge_expired(), 2);
+        cache.insert("f", 7);
+        cache.clear();
+
+        let events = log.lock().clone();
+        let expected = [
+            ("a", 1, EvictionCause::Replaced),
+            ("a", 2, EvictionCause::Capacity),
+            ("b", 3, EvictionCause::Invalidated),
+            ("c", 4, EvictionCause::Invalidated),
+        ];
+        crate::assert_with_log!(
+            events[..4] == expected,
+            "replacement, capacity and invalidation",
+            expected,
+            events
+        );
+        let mut expired = events[4..6].to_vec();
+        expired.sort_unstable_by_key(|(key, _, _)| *key);
+        assert_eq!(
+            expired,
+            [("d", 5, EvictionCause::Expired), ("e", 6, EvictionCause::Expired)]
+        );
+        assert_eq!(events[6..], [("f", 7, EvictionCause::Invalidated)]);
+        assert_eq!(cache.metrics().evictions, 3, "capacity and expiry only");
+        crate::test_complete!("eviction_listener_sees_every_cause");
+    }
+
+    #[test]
+    fn concurrent_loads_and_invalidations_stay_consistent() {
+        init_test("concurrent_loads_and_invalidations_stay_consistent");
+        for seed in 0..8 {
+            let ((results, len, metrics), report) = run_async_under_lab(seed, |cx| async move {
+                let cache = Cache::<u32, u32>::builder().max_entries(4).build();
+                let mut handles = Vec::new();
+                for caller in 0..24_u32 {
+                    let cache = cache.clone();
+                    let handle = cx
+                        .spawn(move |cx| async move {
+                            let key = caller % 6;
+                            if caller % 5 == 0 {
+                                cache.invalidate(&key);
+                            }
+                            let loader = move || async move {
+                                yield_times((key % 3) as usize).await;
+                                Ok(key * 10)
+                            };
+                            (key, cache.get_or_load(&cx, key, loader).await)
+                        })
+                        .expect("spawn caller");
+                    handles.push(handle);
+                }
+                let mut results = Vec::new();
+                for mut handle in handles {
+                    results.push(handle.join(&cx).await.expect("caller finished"));
+                }
+                (results, cache.len(), cache.metrics())
+            });
+
+            assert!(report.quiescent);
+            assert!(
+                results.iter().all(|(key, result)| *result == Ok(key * 10)),
+                "seed {seed}: every caller sees its key's value"
+            );
+            crate::assert_with_log!(len <= 4, "entry bound holds", "<= 4", len);
+            assert_eq!(metrics.hits + metrics.misses, 24);
+            assert!(metrics.loads <= metrics.misses);
+        }
+        crate::test_complete!("concurrent_loads_and_invalidations_stay_consistent");
+    }
+}
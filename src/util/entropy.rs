@@ -26,8 +26,22 @@ pub trait EntropySource: std::fmt::Debug + Send + Sync + 'static {
 
     /// Stable identifier for tracing and diagnostics.
     fn source_id(&self) -> &'static str;
+
+    /// Seed for the task random streams built on this source
+    /// ([`Cx::rng`](crate::cx::Cx::rng)).
+    ///
+    /// Deterministic sources derive it from their own seed without
+    /// consuming a draw, so streams replay with the source. The default
+    /// draws a fresh value.
+    fn stream_seed(&self) -> u64 {
+        self.next_u64()
+    }
 }
 
+/// Domain separator between a deterministic source's draws and its
+/// stream seed.
+const STREAM_SEED_DOMAIN: u64 = 0x7374_7265_616d_7321;
+
 /// OS-backed entropy source for production use.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct OsEntropy;
@@ -95,7 +109,7 @@ impl DetEntropy {
     }
 
     #[inline]
-    fn task_seed(task_id: TaskId) -> u64 {
+    pub(crate) fn task_seed(task_id: TaskId) -> u64 {
         let idx = task_id.arena_index();
         ((u64::from(idx.generation())) << 32) | u64::from(idx.index())
     }
@@ -141,6 +155,63 @@ impl EntropySource for DetEntropy {
     fn source_id(&self) -> &'static str {
         "deterministic"
     }
+
+    #[inline]
+    fn stream_seed(&self) -> u64 {
+        Self::mix_seed(self.seed ^ STREAM_SEED_DOMAIN)
+    }
+}
+
+/// Entropy source whose task random streams use a fixed seed.
+///
+/// Raw draws and forks delegate to the wrapped source, so key material
+/// keeps its entropy; only [`EntropySource::stream_seed`] is pinned. Each
+/// task's streams then depend on the seed and the task's identity alone.
+/// Installed by
+/// [`RuntimeBuilder::rng_seed`](crate::runtime::RuntimeBuilder::rng_seed).
+#[derive(Debug)]
+pub struct SeededStreams {
+    inner: Arc<dyn EntropySource>,
+    seed: u64,
+}
+
+impl SeededStreams {
+    /// Wraps `inner`, pinning stream seeds to `seed`.
+    #[inline]
+    #[must_use]
+    pub fn new(inner: Arc<dyn EntropySource>, seed: u64) -> Self {
+        Self { inner, seed }
+    }
+}
+
+impl EntropySource for SeededStreams {
+    #[inline]
+    fn fill_bytes(&self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest);
+    }
+
+    #[inline]
+    fn next_u64(&self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    #[inline]
+    fn fork(&self, task_id: TaskId) -> Arc<dyn EntropySource> {
+        Arc::new(Self {
+            inner: self.inner.fork(task_id),
+            seed: self.seed,
+        })
+    }
+
+    #[inline]
+    fn source_id(&self) -> &'static str {
+        self.inner.source_id()
+    }
+
+    #[inline]
+    fn stream_seed(&self) -> u64 {
+        DetEntropy::mix_seed(self.seed ^ STREAM_SEED_DOMAIN)
+    }
 }
 
 /// Browser-labeled entropy source for browser-facing capability plumbing.
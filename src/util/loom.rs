@@ -0,0 +1,55 @@
+//! Synchronization primitives that switch to `loom` models under `cfg(loom)`.
+//!
+//! The core synchronization protocols (mpsc, oneshot, semaphore, and the task
+//! wake-dedup state) import their atomics and mutex from here rather than from
+//! `std` or `parking_lot` directly. Normal builds re-export the real types, so
+//! this module has no runtime cost. Building with `RUSTFLAGS="--cfg loom"` and
+//! the `loom-tests` feature swaps in loom's instrumented types, which lets
+//! `tests/sync_loom.rs` explore every interleaving of those protocols.
+//!
+//! Loom types panic when used outside `loom::model`, so a `cfg(loom)` build is
+//! only meaningful for the loom test targets.
+
+#[cfg(all(loom, not(feature = "loom-tests")))]
+compile_error!("building with `--cfg loom` requires the `loom-tests` feature");
+
+/// Real synchronization primitives for normal builds.
+#[cfg(not(loom))]
+pub mod sync {
+    pub use parking_lot::Mutex;
+
+    /// Atomic types used by the modelled protocols.
+    pub mod atomic {
+        pub use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+    }
+}
+
+/// Loom-instrumented synchronization primitives.
+#[cfg(loom)]
+pub mod sync {
+    /// Atomic types used by the modelled protocols.
+    pub mod atomic {
+        pub use ::loom::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+    }
+
+    /// A loom mutex with the `parking_lot` locking API.
+    ///
+    /// Call sites keep using `lock()` without unwrapping; poisoning is ignored
+    /// the same way `parking_lot` ignores it.
+    #[derive(Debug, Default)]
+    pub struct Mutex<T>(::loom::sync::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        /// Creates a new mutex.
+        pub fn new(value: T) -> Self {
+            Self(::loom::sync::Mutex::new(value))
+        }
+
+        /// Acquires the mutex, blocking the current loom thread.
+        pub fn lock(&self) -> ::loom::sync::MutexGuard<'_, T> {
+            self.0
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        }
+    }
+}
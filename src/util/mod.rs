@@ -8,9 +8,11 @@ pub mod cache;
 pub mod det_hash;
 pub mod det_rng;
 pub mod entropy;
+pub(crate) mod loom;
 pub mod path_security;
 pub mod pool;
 pub mod resource;
+pub mod singleflight;
 pub mod stack_trace;
 
 pub use arena::{Arena, ArenaIndex};
@@ -18,8 +20,9 @@ pub use cache::{CACHE_LINE_SIZE, CachePadded};
 pub use det_hash::{DetBuildHasher, DetHashMap, DetHashSet, DetHasher};
 pub use det_rng::DetRng;
 pub use entropy::{
-    BrowserEntropy, DetEntropy, EntropySource, OsEntropy, StrictEntropyGuard, ThreadLocalEntropy,
-    check_ambient_entropy, disable_strict_entropy, enable_strict_entropy, strict_entropy_enabled,
+    BrowserEntropy, DetEntropy, EntropySource, OsEntropy, SeededStreams, StrictEntropyGuard,
+    ThreadLocalEntropy, check_ambient_entropy, disable_strict_entropy, enable_strict_entropy,
+    strict_entropy_enabled,
 };
 pub use path_security::{PathSecurityError, SecurePath, ValidatedPath};
 pub use pool::{Pool, Recyclable, RecyclingPool};
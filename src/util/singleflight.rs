@@ -0,0 +1,665 @@
+//! Request coalescing keyed by request identity.
+//!
+//! A [`Group`] deduplicates concurrent calls that share a key: the first
+//! caller (the leader) runs its operation, and every caller that arrives while
+//! it is in flight (the followers) waits for the same result instead of
+//! issuing a duplicate request. The entry is removed as soon as the operation
+//! completes, so a later call runs fresh unless a TTL is configured.
+//!
+//! # Cancellation
+//!
+//! Dropping the leader's future abandons its operation but not the request.
+//! The longest-waiting follower is promoted and runs its own operation, so a
+//! cancelled leader never fails the callers waiting on it. Promotion is by
+//! arrival order, which keeps it deterministic under the lab runtime.
+//!
+//! # Panics
+//!
+//! A panic inside the leader's operation is caught and delivered to the leader
+//! and every follower as [`SingleflightError::Panicked`]; nobody hangs. Under
+//! `panic = "abort"` the panic ends the process like any other.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use asupersync::util::singleflight::Group;
+//!
+//! let lookups: Group<String, Vec<IpAddr>> = Group::new();
+//! let addrs = lookups.run(host.clone(), || resolve(host)).await?;
+//! ```
+
+use crate::runtime::panic_strategy;
+use crate::time::{TimeSource, WallClock};
+use crate::types::{PanicPayload, Time};
+use crate::util::DetHashMap;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::hash::Hash;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Error returned by [`Group::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SingleflightError {
+    /// The operation panicked while running for the leader.
+    Panicked(PanicPayload),
+}
+
+impl fmt::Display for SingleflightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panicked(payload) => write!(f, "coalesced operation failed: {payload}"),
+        }
+    }
+}
+
+impl std::error::Error for SingleflightError {}
+
+/// Who is responsible for running the operation of an in-flight call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// A caller is running its operation.
+    Running,
+    /// The previous runner was cancelled; this follower takes over next.
+    Promoted(u64),
+    /// The runner was cancelled with nobody waiting; the entry is retired.
+    Abandoned,
+}
+
+struct CallState<V> {
+    role: Role,
+    outcome: Option<Result<V, SingleflightError>>,
+    /// Waiting followers keyed by arrival order.
+    followers: BTreeMap<u64, Option<Waker>>,
+    next_follower: u64,
+}
+
+/// One in-flight call. Lock order: the group lock before the call lock.
+struct Call<V> {
+    state: Mutex<CallState<V>>,
+}
+
+struct Cached<V> {
+    value: V,
+    expires_at: Time,
+}
+
+struct GroupState<K, V> {
+    calls: DetHashMap<K, Arc<Call<V>>>,
+    cache: DetHashMap<K, Cached<V>>,
+}
+
+struct Ttl {
+    duration: Duration,
+    clock: Arc<dyn TimeSource>,
+}
+
+enum Join<V> {
+    Cached(V),
+    Leader(Arc<Call<V>>),
+    Follower(Arc<Call<V>>, u64),
+}
+
+enum Wait<V> {
+    Done(Result<V, SingleflightError>),
+    Promoted,
+}
+
+/// Coalesces concurrent calls with equal keys into one execution.
+pub struct Group<K, V> {
+    state: Mutex<GroupState<K, V>>,
+    ttl: Option<Ttl>,
+}
+
+impl<K, V> fmt::Debug for Group<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Group")
+            .field("in_flight", &state.calls.len())
+            .field("cached", &state.cache.len())
+            .field("ttl", &self.ttl.as_ref().map(|ttl| ttl.duration))
+            .finish()
+    }
+}
+
+impl<K, V> Default for Group<K, V> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(GroupState {
+                calls: DetHashMap::default(),
+                cache: DetHashMap::default(),
+            }),
+            ttl: None,
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Group<K, V> {
+    /// Creates a group that shares results only between concurrent callers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a group that also caches successful results for `ttl`,
+    /// measured with the wall clock.
+    #[must_use]
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self::with_ttl_and_clock(ttl, Arc::new(WallClock::new()))
+    }
+
+    /// Creates a group that caches successful results for `ttl`, measured
+    /// with `clock`.
+    ///
+    /// Pass a [`VirtualClock`](crate::time::VirtualClock) to make expiry
+    /// deterministic in tests.
+    #[must_use]
+    pub fn with_ttl_and_clock(ttl: Duration, clock: Arc<dyn TimeSource>) -> Self {
+        Self {
+            ttl: Some(Ttl {
+                duration: ttl,
+                clock,
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of keys with an operation in flight.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().calls.len()
+    }
+
+    /// Returns the number of cached results, including expired ones that
+    /// have not been evicted yet.
+    #[must_use]
+    pub fn cached(&self) -> usize {
+        self.state.lock().cache.len()
+    }
+
+    /// Drops the cached result for `key`, if any.
+    pub fn forget(&self, key: &K) {
+        self.state.lock().cache.remove(key);
+    }
+
+    /// Runs `operation` for `key`, or joins the call already in flight.
+    ///
+    /// The leader calls `operation`; followers drop theirs unless they are
+    /// promoted after the leader is cancelled. Every caller receives a clone
+    /// of the same result.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SingleflightError::Panicked`] if the operation that produced
+    /// the shared result panicked.
+    pub async fn run<F, Fut>(&self, key: K, operation: F) -> Result<V, SingleflightError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let call = match self.join(&key) {
+            Join::Cached(value) => return Ok(value),
+            Join::Leader(call) => call,
+            Join::Follower(call, id) => {
+                let mut follower = FollowerGuard {
+                    group: self,
+                    key: &key,
+                    call: &call,
+                    id,
+                    active: true,
+                };
+                let wait = poll_fn(|task| follower.poll_wait(task)).await;
+                follower.active = false;
+                match wait {
+                    Wait::Done(result) => return result,
+                    Wait::Promoted => Arc::clone(&call),
+                }
+            }
+        };
+
+        let mut leader = LeaderGuard {
+            group: self,
+            key: &key,
+            call: &call,
+            active: true,
+        };
+        let result = run_catching(operation).await;
+        leader.active = false;
+        self.complete(&key, &call, result.clone());
+        result
+    }
+
+    fn join(&self, key: &K) -> Join<V> {
+        let mut state = self.state.lock();
+
+        if let Some(ttl) = &self.ttl
+            && let Some(cached) = state.cache.get(key)
+        {
+            if cached.expires_at > ttl.clock.now() {
+                return Join::Cached(cached.value.clone());
+            }
+            state.cache.remove(key);
+        }
+
+        if let Some(call) = state.calls.get(key) {
+            let call = Arc::clone(call);
+            let mut call_state = call.state.lock();
+            drop(state);
+            let id = call_state.next_follower;
+            call_state.next_follower += 1;
+            call_state.followers.insert(id, None);
+            drop(call_state);
+            return Join::Follower(call, id);
+        }
+
+        let call = Arc::new(Call {
+            state: Mutex::new(CallState {
+                role: Role::Running,
+                outcome: None,
+                followers: BTreeMap::new(),
+                next_follower: 0,
+            }),
+        });
+        state.calls.insert(key.clone(), Arc::clone(&call));
+        drop(state);
+        Join::Leader(call)
+    }
+
+    /// Publishes the result, retires the entry, and wakes every follower.
+    fn complete(&self, key: &K, call: &Arc<Call<V>>, result: Result<V, SingleflightError>) {
+        let mut state = self.state.lock();
+        if state
+            .calls
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, call))
+        {
+            state.calls.remove(key);
+        }
+        if let (Some(ttl), Ok(value)) = (&self.ttl, &result) {
+            let now = ttl.clock.now();
+            state.cache.retain(|_, cached| cached.expires_at > now);
+            let expires_at = now.saturating_add_nanos(duration_to_nanos_saturating(ttl.duration));
+            state.cache.insert(
+                key.clone(),
+                Cached {
+                    value: value.clone(),
+                    expires_at,
+                },
+            );
+        }
+
+        let mut call_state = call.state.lock();
+        drop(state);
+        call_state.outcome = Some(result);
+        let wakers: Vec<Waker> = call_state
+            .followers
+            .values_mut()
+            .filter_map(Option::take)
+            .collect();
+        drop(call_state);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Hands the call to the longest-waiting follower, or abandons it.
+    ///
+    /// Must be called with the group lock held.
+    fn hand_off(
+        state: &mut GroupState<K, V>,
+        key: &K,
+        call: &Arc<Call<V>>,
+        call_state: &mut CallState<V>,
+    ) -> Option<Waker> {
+        if let Some((&next, waker)) = call_state.followers.iter_mut().next() {
+            call_state.role = Role::Promoted(next);
+            return waker.take();
+        }
+        call_state.role = Role::Abandoned;
+        if state
+            .calls
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, call))
+        {
+            state.calls.remove(key);
+        }
+        None
+    }
+}
+
+/// Releases the leader role if the leader's future is dropped mid-flight.
+struct LeaderGuard<'a, K: Eq + Hash + Clone, V: Clone> {
+    group: &'a Group<K, V>,
+    key: &'a K,
+    call: &'a Arc<Call<V>>,
+    active: bool,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Drop for LeaderGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let mut state = self.group.state.lock();
+        let mut call_state = self.call.state.lock();
+        let waker = Group::<K, V>::hand_off(&mut state, self.key, self.call, &mut call_state);
+        drop(call_state);
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Deregisters a follower whose future is dropped while waiting.
+struct FollowerGuard<'a, K: Eq + Hash + Clone, V: Clone> {
+    group: &'a Group<K, V>,
+    key: &'a K,
+    call: &'a Arc<Call<V>>,
+    id: u64,
+    active: bool,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> FollowerGuard<'_, K, V> {
+    fn poll_wait(&self, task: &Context<'_>) -> Poll<Wait<V>> {
+        let mut call_state = self.call.state.lock();
+        if let Some(outcome) = &call_state.outcome {
+            let outcome = outcome.clone();
+            call_state.followers.remove(&self.id);
+            return Poll::Ready(Wait::Done(outcome));
+        }
+        if call_state.role == Role::Promoted(self.id) {
+            call_state.role = Role::Running;
+            call_state.followers.remove(&self.id);
+            return Poll::Ready(Wait::Promoted);
+        }
+        if let Some(slot) = call_state.followers.get_mut(&self.id) {
+            *slot = Some(task.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Drop for FollowerGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let mut state = self.group.state.lock();
+        let mut call_state = self.call.state.lock();
+        call_state.followers.remove(&self.id);
+        let waker = if call_state.role == Role::Promoted(self.id) && call_state.outcome.is_none() {
+            Group::<K, V>::hand_off(&mut state, self.key, self.call, &mut call_state)
+        } else {
+            None
+        };
+        drop(call_state);
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Runs `operation`, converting a panic during construction or polling into
+/// [`SingleflightError::Panicked`].
+async fn run_catching<F, Fut, V>(operation: F) -> Result<V, SingleflightError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = V>,
+{
+    let future = match panic_strategy::catch_unwind(operation) {
+        Ok(future) => future,
+        Err(payload) => return Err(panicked(payload.as_ref())),
+    };
+    let mut future = pin!(future);
+    poll_fn(|task| match panic_strategy::catch_unwind(|| future.as_mut().poll(task)) {
+        Ok(Poll::Ready(value)) => Poll::Ready(Ok(value)),
+        Ok(Poll::Pending) => Poll::Pending,
+        Err(payload) => Poll::Ready(Err(panicked(payload.as_ref()))),
+    })
+    .await
+}
+
+fn panicked(payload: &(dyn Any + Send)) -> SingleflightError {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string());
+    SingleflightError::Panicked(PanicPayload::new(message))
+}
+
+fn duration_to_nanos_saturating(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(
+        clippy::pedantic,
+        clippy::nursery,
+        clippy::expect_fun_call,
+        clippy::map_unwrap_or,
+        clippy::cast_possible_wrap,
+        clippy::future_not_send
+    )]
+    use super::*;
+    use crate::lab::runtime::test as lab_test;
+    use crate::runtime::yield_now;
+    use crate::time::VirtualClock;
+    use crate::types::Budget;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn init_test(name: &str) {
+        crate::test_utils::init_test_logging();
+        crate::test_phase!(name);
+    }
+
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        let mut task_cx = Context::from_waker(Waker::noop());
+        future.poll(&mut task_cx)
+    }
+
+    #[test]
+    fn concurrent_callers_share_one_execution() {
+        init_test("concurrent_callers_share_one_execution");
+        for seed in 0..8 {
+            let executions = Arc::new(AtomicUsize::new(0));
+            let results = Arc::new(Mutex::new(Vec::new()));
+            let group: Arc<Group<&'static str, u64>> = Arc::new(Group::new());
+
+            lab_test(seed, |lab| {
+                let root = lab.state.create_root_region(Budget::INFINITE);
+                let mut tasks = Vec::new();
+                for _ in 0..16 {
+                    let group = Arc::clone(&group);
+                    let executions = Arc::clone(&executions);
+                    let results = Arc::clone(&results);
+                    let (task, _) = lab
+                        .state
+                        .create_task(root, Budget::INFINITE, async move {
+                            let value = group
+                                .run("config", || async move {
+                                    executions.fetch_add(1, Ordering::SeqCst);
+                                    for _ in 0..4 {
+                                        yield_now().await;
+                                    }
+                                    42
+                                })
+                                .await;
+                            results.lock().push(value);
+                        })
+                        .expect("spawn caller");
+                    tasks.push(task);
+                }
+                for task in tasks {
+                    lab.scheduler.lock().schedule(task, 0);
+                }
+                lab.run_until_quiescent();
+            });
+
+            let results = results.lock();
+            crate::assert_with_log!(
+                executions.load(Ordering::SeqCst) == 1,
+                "one execution",
+                1,
+                executions.load(Ordering::SeqCst)
+            );
+            assert_eq!(results.len(), 16);
+            assert!(results.iter().all(|result| *result == Ok(42)));
+            assert_eq!(group.in_flight(), 0, "entry retired after completion");
+        }
+        crate::test_complete!("concurrent_callers_share_one_execution");
+    }
+
+    #[test]
+    fn cancelled_leader_promotes_oldest_follower() {
+        init_test("cancelled_leader_promotes_oldest_follower");
+        let group: Group<u32, &'static str> = Group::new();
+        let runs = Mutex::new(Vec::new());
+        let runs = &runs;
+
+        let mut leader = Box::pin(group.run(7, || async move {
+            runs.lock().push("leader");
+            std::future::pending::<&'static str>().await
+        }));
+        let mut first = Box::pin(group.run(7, || async move {
+            runs.lock().push("first");
+            "from-first"
+        }));
+        let mut second = Box::pin(group.run(7, || async move {
+            runs.lock().push("second");
+            "from-second"
+        }));
+
+        assert!(poll_once(leader.as_mut()).is_pending());
+        assert!(poll_once(first.as_mut()).is_pending());
+        assert!(poll_once(second.as_mut()).is_pending());
+
+        drop(leader);
+        // Only the oldest follower is promoted; the other keeps waiting.
+        assert!(poll_once(second.as_mut()).is_pending());
+        assert_eq!(poll_once(first.as_mut()), Poll::Ready(Ok("from-first")));
+        assert_eq!(poll_once(second.as_mut()), Poll::Ready(Ok("from-first")));
+
+        crate::assert_with_log!(
+            *runs.lock() == ["leader", "first"],
+            "promoted follower re-ran the operation",
+            ["leader", "first"],
+            runs.lock().clone()
+        );
+        assert_eq!(group.in_flight(), 0);
+        crate::test_complete!("cancelled_leader_promotes_oldest_follower");
+    }
+
+    #[test]
+    fn cancelled_promoted_follower_hands_off_again() {
+        init_test("cancelled_promoted_follower_hands_off_again");
+        let group: Group<u32, u32> = Group::new();
+
+        let mut leader = Box::pin(group.run(1, || std::future::pending::<u32>()));
+        let mut first = Box::pin(group.run(1, || async { 1 }));
+        let mut second = Box::pin(group.run(1, || async { 2 }));
+        assert!(poll_once(leader.as_mut()).is_pending());
+        assert!(poll_once(first.as_mut()).is_pending());
+        assert!(poll_once(second.as_mut()).is_pending());
+
+        drop(leader);
+        drop(first);
+        assert_eq!(poll_once(second.as_mut()), Poll::Ready(Ok(2)));
+        assert_eq!(group.in_flight(), 0);
+
+        // A cancelled leader with nobody waiting retires the entry.
+        let mut lone = Box::pin(group.run(1, || std::future::pending::<u32>()));
+        assert!(poll_once(lone.as_mut()).is_pending());
+        assert_eq!(group.in_flight(), 1);
+        drop(lone);
+        assert_eq!(group.in_flight(), 0);
+        crate::test_complete!("cancelled_promoted_follower_hands_off_again");
+    }
+
+    #[test]
+    fn leader_panic_reaches_followers_as_error() {
+        init_test("leader_panic_reaches_followers_as_error");
+        let group: Group<&'static str, u32> = Group::new();
+
+        let mut leader = Box::pin(group.run("boom", || async {
+            yield_now().await;
+            let explode = true;
+            if explode {
+                panic!("lookup exploded");
+            }
+            0
+        }));
+        let mut follower = Box::pin(group.run("boom", || async { 0 }));
+
+        assert!(poll_once(leader.as_mut()).is_pending());
+        assert!(poll_once(follower.as_mut()).is_pending());
+
+        let Poll::Ready(Err(SingleflightError::Panicked(payload))) = poll_once(leader.as_mut())
+        else {
+            panic!("leader should observe its own panic as an error");
+        };
+        assert_eq!(payload.message(), "lookup exploded");
+        assert_eq!(
+            poll_once(follower.as_mut()),
+            Poll::Ready(Err(SingleflightError::Panicked(payload)))
+        );
+        assert_eq!(group.in_flight(), 0);
+        crate::test_complete!("leader_panic_reaches_followers_as_error");
+    }
+
+    #[test]
+    fn ttl_caches_success_until_expiry() {
+        init_test("ttl_caches_success_until_expiry");
+        let clock = Arc::new(VirtualClock::starting_at(Time::from_secs(100)));
+        let group: Group<&'static str, u32> =
+            Group::with_ttl_and_clock(Duration::from_secs(10), clock.clone());
+        let executions = AtomicUsize::new(0);
+        let executions = &executions;
+        let fetch = || async move { executions.fetch_add(1, Ordering::SeqCst) as u32 + 1 };
+
+        let mut first = Box::pin(group.run("key", fetch));
+        assert_eq!(poll_once(first.as_mut()), Poll::Ready(Ok(1)));
+        drop(first);
+        assert_eq!(group.in_flight(), 0);
+        assert_eq!(group.cached(), 1);
+
+        clock.advance(Duration::from_secs(9).as_nanos() as u64);
+        let mut cached = Box::pin(group.run("key", fetch));
+        assert_eq!(poll_once(cached.as_mut()), Poll::Ready(Ok(1)));
+        drop(cached);
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+
+        clock.advance(Duration::from_secs(1).as_nanos() as u64);
+        let mut expired = Box::pin(group.run("key", fetch));
+        assert_eq!(poll_once(expired.as_mut()), Poll::Ready(Ok(2)));
+        drop(expired);
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+
+        group.forget(&"key");
+        assert_eq!(group.cached(), 0);
+        crate::test_complete!("ttl_caches_success_until_expiry");
+    }
+
+    #[test]
+    fn distinct_keys_run_independently() {
+        init_test("distinct_keys_run_independently");
+        let group: Group<u32, u32> = Group::new();
+        let mut a = Box::pin(group.run(1, || std::future::pending::<u32>()));
+        let mut b = Box::pin(group.run(2, || async { 20 }));
+        assert!(poll_once(a.as_mut()).is_pending());
+        assert_eq!(poll_once(b.as_mut()), Poll::Ready(Ok(20)));
+        assert_eq!(group.in_flight(), 1);
+        drop(a);
+        assert_eq!(group.in_flight(), 0);
+        crate::test_complete!("distinct_keys_run_independently");
+    }
+}
@@ -13,6 +13,7 @@
 //! - `GET /debug/snapshot` — Current runtime snapshot as JSON
 //! - `GET /debug/memory-residency` — Memory-residency accounting snapshot as JSON
 //! - `GET /debug/trace` — Recent trace events as JSON
+//! - `GET /debug/suptree` — Supervision tree as JSON (`?format=dot` for Graphviz)
 //! - `GET /debug/ws` — WebSocket endpoint (upgrade + one-shot JSON push)
 //!
 //! # Example
@@ -32,6 +33,16 @@
 //! server.start().expect("failed to start debug server");
 //! println!("Dashboard: {}", server.url());
 //! ```
+//!
+//! The supervision tree endpoint is served from an additive provider:
+//!
+//! ```ignore
+//! let st = Arc::clone(&state);
+//! let server = server.with_supervision_tree_snapshot_fn(Arc::new(move || {
+//!     let state = st.lock();
+//!     state.supervision_tree().snapshot(&state)
+//! }));
+//! ```
 
 use std::io::{BufRead, BufReader, Write};
 use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
@@ -41,6 +52,7 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
 
 use crate::runtime::{MemoryResidencyAccountingSnapshot, RuntimeSnapshot};
+use crate::supervision::{SUPERVISION_TREE_SCHEMA_VERSION, SupervisionTreeSnapshot};
 use crate::tracing_compat::info;
 use base64::Engine as _;
 use sha1::{Digest, Sha1};
@@ -52,6 +64,9 @@ pub type SnapshotFn = Arc<dyn Fn() -> RuntimeSnapshot + Send + Sync>;
 pub type MemoryResidencySnapshotFn =
     Arc<dyn Fn() -> MemoryResidencyAccountingSnapshot + Send + Sync>;
 
+/// Function that produces a supervision tree snapshot on demand.
+pub type SupervisionTreeSnapshotFn = Arc<dyn Fn() -> SupervisionTreeSnapshot + Send + Sync>;
+
 /// Configuration for the debug server.
 #[derive(Debug, Clone)]
 pub struct DebugServerConfig {
@@ -85,6 +100,7 @@ pub struct DebugServer {
     port: u16,
     snapshot_fn: SnapshotFn,
     memory_residency_snapshot_fn: MemoryResidencySnapshotFn,
+    supervision_tree_snapshot_fn: SupervisionTreeSnapshotFn,
     config: DebugServerConfig,
     running: Arc<AtomicBool>,
     local_addr: Option<SocketAddr>,
@@ -98,6 +114,7 @@ impl DebugServer {
             port,
             snapshot_fn,
             memory_residency_snapshot_fn: Self::default_memory_residency_snapshot_fn(),
+            supervision_tree_snapshot_fn: Self::default_supervision_tree_snapshot_fn(),
             config: DebugServerConfig::default(),
             running: Arc::new(AtomicBool::new(false)),
             local_addr: None,
@@ -111,6 +128,7 @@ impl DebugServer {
             port,
             snapshot_fn,
             memory_residency_snapshot_fn: Self::default_memory_residency_snapshot_fn(),
+            supervision_tree_snapshot_fn: Self::default_supervision_tree_snapshot_fn(),
             config,
             running: Arc::new(AtomicBool::new(false)),
             local_addr: None,
@@ -131,6 +149,26 @@ impl DebugServer {
         Arc::new(|| MemoryResidencyAccountingSnapshot::unavailable(0))
     }
 
+    /// Installs an additive supervision tree snapshot provider.
+    ///
+    /// Without one, `/debug/suptree` serves an empty tree.
+    #[must_use]
+    pub fn with_supervision_tree_snapshot_fn(
+        mut self,
+        snapshot_fn: SupervisionTreeSnapshotFn,
+    ) -> Self {
+        self.supervision_tree_snapshot_fn = snapshot_fn;
+        self
+    }
+
+    fn default_supervision_tree_snapshot_fn() -> SupervisionTreeSnapshotFn {
+        Arc::new(|| SupervisionTreeSnapshot {
+            schema_version: SUPERVISION_TREE_SCHEMA_VERSION,
+            timestamp: 0,
+            supervisors: Vec::new(),
+        })
+    }
+
     /// Returns the dashboard URL.
     #[must_use]
     pub fn url(&self) -> String {
@@ -176,6 +214,7 @@ impl DebugServer {
 
         let snapshot_fn = Arc::clone(&self.snapshot_fn);
         let memory_residency_snapshot_fn = Arc::clone(&self.memory_residency_snapshot_fn);
+        let supervision_tree_snapshot_fn = Arc::clone(&self.supervision_tree_snapshot_fn);
         let running = Arc::clone(&self.running);
         let active_connections = Arc::new(AtomicUsize::new(0));
         let max_connections = self.config.max_connections;
@@ -187,6 +226,7 @@ impl DebugServer {
                     &listener,
                     &snapshot_fn,
                     &memory_residency_snapshot_fn,
+                    &supervision_tree_snapshot_fn,
                     &running,
                     max_connections,
                     &active_connections,
@@ -216,6 +256,7 @@ fn serve_loop(
     listener: &TcpListener,
     snapshot_fn: &SnapshotFn,
     memory_residency_snapshot_fn: &MemoryResidencySnapshotFn,
+    supervision_tree_snapshot_fn: &SupervisionTreeSnapshotFn,
     running: &AtomicBool,
     max_connections: usize,
     active_connections: &Arc<AtomicUsize>,
@@ -237,6 +278,7 @@ fn serve_loop(
                 let _ = stream.set_write_timeout(Some(std::time::Duration::from_secs(5)));
                 let snapshot_fn = Arc::clone(snapshot_fn);
                 let memory_residency_snapshot_fn = Arc::clone(memory_residency_snapshot_fn);
+                let supervision_tree_snapshot_fn = Arc::clone(supervision_tree_snapshot_fn);
                 let active_connections_for_thread = Arc::clone(active_connections);
 
                 if thread::Builder::new()
@@ -245,7 +287,12 @@ fn serve_loop(
                         let _active_connection =
                             ActiveConnectionGuard::new(Arc::clone(&active_connections_for_thread));
                         let _ = panic::catch_unwind(AssertUnwindSafe(|| {
-                            handle_connection(stream, &snapshot_fn, &memory_residency_snapshot_fn);
+                            handle_connection(
+                                stream,
+                                &snapshot_fn,
+                                &memory_residency_snapshot_fn,
+                                &supervision_tree_snapshot_fn,
+                            );
                         }));
                     })
                     .is_err()
@@ -288,6 +335,7 @@ fn handle_connection(
     mut stream: TcpStream,
     snapshot_fn: &SnapshotFn,
     memory_residency_snapshot_fn: &MemoryResidencySnapshotFn,
+    supervision_tree_snapshot_fn: &SupervisionTreeSnapshotFn,
 ) {
     let mut reader = if let Ok(read_half) = stream.try_clone() {
         BufReader::new(read_half)
@@ -309,9 +357,9 @@ fn handle_connection(
     }
     let method = parts[0];
     let request_target = parts[1];
-    let path = request_target
+    let (path, query) = request_target
         .split_once('?')
-        .map_or(request_target, |(path, _query)| path);
+        .unwrap_or((request_target, ""));
     let headers = read_headers(&mut reader);
 
     // Only handle GET requests.
@@ -366,6 +414,29 @@ fn handle_connection(
                 }
             }
         }
+        "/debug/suptree" => {
+            let snapshot = supervision_tree_snapshot_fn();
+            if query_param(query, "format") == Some("dot") {
+                let _ = write_response(
+                    &mut stream,
+                    200,
+                    "text/vnd.graphviz; charset=utf-8",
+                    snapshot.to_dot().as_bytes(),
+                );
+            } else {
+                match serde_json::to_string_pretty(&snapshot) {
+                    Ok(json) => {
+                        let _ =
+                            write_response(&mut stream, 200, "application/json", json.as_bytes());
+                    }
+                    Err(e) => {
+                        let body = format!("{{\"error\":\"{e}\"}}");
+                        let _ =
+                            write_response(&mut stream, 500, "application/json", body.as_bytes());
+                    }
+                }
+            }
+        }
         "/debug/ws" => {
             if let Err(err) = handle_websocket(&mut stream, &headers, snapshot_fn) {
                 let body = format!("{{\"error\":\"websocket upgrade failed: {err}\"}}");
@@ -411,6 +482,14 @@ fn read_headers<R: BufRead>(reader: &mut R) -> Vec<(String, String)> {
     headers
 }
 
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
 fn header_value<'a>(headers: &'a [(String, String)], key: &str) -> Option<&'a str> {
     headers
         .iter()
@@ -796,6 +875,47 @@ mod tests {
         server.stop();
     }
 
+    #[test]
+    fn server_returns_supervision_tree_json_and_dot() {
+        let snapshot_fn: SnapshotFn = Arc::new(test_snapshot);
+        let mut server = DebugServer::with_config(
+            0,
+            snapshot_fn,
+            DebugServerConfig {
+                print_url: false,
+                ..Default::default()
+            },
+        )
+        .with_supervision_tree_snapshot_fn(Arc::new(|| SupervisionTreeSnapshot {
+            schema_version: SUPERVISION_TREE_SCHEMA_VERSION,
+            timestamp: 4242,
+            supervisors: Vec::new(),
+        }));
+        server.start().unwrap();
+        let addr = server.local_addr.unwrap();
+
+        let fetch = |target: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            stream.flush().unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let json = fetch("/debug/suptree");
+        assert!(json.contains("200 OK"));
+        assert!(json.contains("application/json"));
+        assert!(json.contains("\"timestamp\": 4242"));
+
+        let dot = fetch("/debug/suptree?format=dot");
+        assert!(dot.contains("200 OK"));
+        assert!(dot.contains("text/vnd.graphviz"));
+        assert!(dot.contains("digraph supervision {"));
+
+        server.stop();
+    }
+
     #[test]
     fn websocket_upgrade_and_frame_push() {
         let snapshot_fn: SnapshotFn = Arc::new(test_snapshot);
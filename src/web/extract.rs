@@ -7,6 +7,7 @@
 //! # Built-in Extractors
 //!
 //! - [`Path<T>`]: URL path parameters
+//! - [`PathParams<T>`]: URL path parameters in pattern order
 //! - [`Query<T>`]: Query string parameters
 //! - [`Json<T>`]: JSON request body
 //! - [`Form<T>`]: URL-encoded form body
@@ -22,6 +23,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::bytes::Bytes;
+use crate::observability::w3c_trace_context::{TraceContextError, TraceId};
 use serde::de::{
     self, DeserializeOwned, DeserializeSeed, IntoDeserializer, SeqAccess, Unexpected, Visitor,
 };
@@ -299,6 +301,200 @@ where
     }
 }
 
+// ─── PathParams<T> ───────────────────────────────────────────────────────────
+
+/// Error returned by a typed path parameter parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathParamError {
+    message: String,
+}
+
+impl PathParamError {
+    /// Create a path parameter parse error.
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Human-readable parse failure.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for PathParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PathParamError {}
+
+/// Convert one percent-decoded path segment into a typed value.
+///
+/// Implement this for identifier newtypes and use them in a one-element
+/// tuple: `PathParams<(OrderId,)>`.
+pub trait FromPathParam: Sized {
+    /// Parse a decoded path segment.
+    fn from_path_param(value: &str) -> Result<Self, PathParamError>;
+}
+
+/// Convert the ordered parameters of a matched route into a typed value.
+///
+/// Implemented for every [`FromPathParam`] scalar in this module (exactly one
+/// parameter) and for tuples of up to six [`FromPathParam`] values, which
+/// bind parameters in the order they appear in the route pattern.
+pub trait FromPathParams: Sized {
+    /// Parse `(name, value)` pairs in route-pattern order.
+    fn from_path_params(params: &[(&str, &str)]) -> Result<Self, ExtractionError>;
+}
+
+/// Extract path parameters positionally through [`FromPathParam`].
+///
+/// Unlike [`Path<T>`], which deserializes by parameter name, this binds the
+/// parameters in the order they appear in the route pattern, so each handler
+/// argument names its own type:
+///
+/// ```ignore
+/// // Route: "/users/:id/orders/:order_id"
+/// async fn order(PathParams((user, order)): PathParams<(u64, u32)>) -> String {
+///     format!("{user}/{order}")
+/// }
+/// ```
+///
+/// A value that fails to parse is a `400 Bad Request` naming the parameter;
+/// a tuple whose arity differs from the route's parameter count is a
+/// `500 Internal Server Error`, since that is a handler/route mismatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathParams<T>(pub T);
+
+impl<T> FromRequestParts for PathParams<T>
+where
+    T: FromPathParams,
+{
+    fn from_request_parts(req: &Request) -> Result<Self, ExtractionError> {
+        let ordered = ordered_path_params(req);
+        T::from_path_params(&ordered).map(Self)
+    }
+}
+
+/// Parameter names of the matched route, in pattern order.
+///
+/// Set by the router next to [`Request::path_params`]. Requests built by hand
+/// with [`Request::with_path_params`] fall back to name order.
+#[derive(Debug, Clone)]
+pub(crate) struct PathParamNames(pub(crate) Arc<[String]>);
+
+fn ordered_path_params(req: &Request) -> Vec<(&str, &str)> {
+    if let Some(PathParamNames(names)) = req.extensions.get_typed::<PathParamNames>() {
+        return names
+            .iter()
+            .filter_map(|name| {
+                req.path_params
+                    .get_key_value(name)
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+            })
+            .collect();
+    }
+    let mut ordered = req
+        .path_params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+    ordered.sort_unstable();
+    ordered
+}
+
+fn parse_path_param<T: FromPathParam>(
+    (name, value): &(&str, &str),
+) -> Result<T, ExtractionError> {
+    T::from_path_param(value).map_err(|err| {
+        ExtractionError::bad_request(format!("invalid path parameter `{name}`: {err}"))
+    })
+}
+
+fn path_param_arity_error(expected: usize, found: usize) -> ExtractionError {
+    ExtractionError::new(
+        super::response::StatusCode::INTERNAL_SERVER_ERROR,
+        format!("handler expects {expected} path parameter(s), route matched {found}"),
+    )
+}
+
+impl FromPathParam for String {
+    fn from_path_param(value: &str) -> Result<Self, PathParamError> {
+        Ok(value.to_string())
+    }
+}
+
+impl FromPathParam for TraceId {
+    fn from_path_param(value: &str) -> Result<Self, PathParamError> {
+        value
+            .parse()
+            .map_err(|err: TraceContextError| PathParamError::new(err.to_string()))
+    }
+}
+
+macro_rules! impl_from_path_param_int {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl FromPathParam for $ty {
+                fn from_path_param(value: &str) -> Result<Self, PathParamError> {
+                    value
+                        .parse()
+                        .map_err(|err: std::num::ParseIntError| {
+                            PathParamError::new(err.to_string())
+                        })
+                }
+            }
+        )+
+    };
+}
+
+impl_from_path_param_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_from_path_params_single {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl FromPathParams for $ty {
+                fn from_path_params(params: &[(&str, &str)]) -> Result<Self, ExtractionError> {
+                    match params {
+                        [param] => parse_path_param(param),
+                        _ => Err(path_param_arity_error(1, params.len())),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_from_path_params_single!(
+    String, TraceId, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+macro_rules! impl_from_path_params_tuple {
+    ($len:literal; $($ty:ident),+) => {
+        impl<$($ty: FromPathParam),+> FromPathParams for ($($ty,)+) {
+            #[allow(non_snake_case)]
+            fn from_path_params(params: &[(&str, &str)]) -> Result<Self, ExtractionError> {
+                let [$($ty),+] = params else {
+                    return Err(path_param_arity_error($len, params.len()));
+                };
+                Ok(($(parse_path_param::<$ty>($ty)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_path_params_tuple!(1; A);
+impl_from_path_params_tuple!(2; A, B);
+impl_from_path_params_tuple!(3; A, B, C);
+impl_from_path_params_tuple!(4; A, B, C, D);
+impl_from_path_params_tuple!(5; A, B, C, D, E);
+impl_from_path_params_tuple!(6; A, B, C, D, E, F);
+
 // ─── Query<T> ────────────────────────────────────────────────────────────────
 
 /// Extract query string parameters.
@@ -1856,6 +2052,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn path_params_without_route_order_bind_by_name() {
+        let mut params = HashMap::new();
+        params.insert("b".to_string(), "2".to_string());
+        params.insert("a".to_string(), "1".to_string());
+        let req = Request::new("GET", "/x/1/2").with_path_params(params);
+
+        let PathParams((a, b)) = PathParams::<(u32, u8)>::from_request_parts(&req).unwrap();
+        assert_eq!((a, b), (1, 2));
+
+        let err = PathParams::<u32>::from_request_parts(&req).unwrap_err();
+        assert_eq!(err.status, crate::web::response::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), "-1".to_string());
+        let req = Request::new("GET", "/x/-1").with_path_params(params);
+        let err = PathParams::<u32>::from_request_parts(&req).unwrap_err();
+        assert_eq!(err.status, crate::web::response::StatusCode::BAD_REQUEST);
+        assert!(err.message.contains("`id`"), "{}", err.message);
+    }
+
     #[test]
     fn percent_decode_preserves_invalid_sequences() {
         assert_eq!(percent_decode("a%2"), "a%2");
@@ -32,9 +32,11 @@ use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Write as _;
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::handler::FnHandler;
 use super::response::{IntoResponse, Response, StatusCode};
+use crate::types::Time;
 
 // ─── HealthStatus ────────────────────────────────────────────────────────────
 
@@ -192,6 +194,86 @@ fn json_escape_into(buf: &mut String, s: &str) {
     }
 }
 
+// ─── DrainNotice ─────────────────────────────────────────────────────────────
+
+/// A clock reading the runtime's notion of "now".
+type ClockFn = Arc<dyn Fn() -> Time + Send + Sync>;
+
+/// Readiness-gate state advertised while the service drains for shutdown.
+///
+/// Installed with [`HealthCheck::begin_draining`]. While a notice is present
+/// the readiness probe answers 503 with:
+///
+/// - `Retry-After`: the configured retry hint, in whole seconds (at least 1).
+/// - `Drain-Deadline`: milliseconds remaining until the server stops
+///   accepting new connections, measured on the notice's clock.
+#[derive(Clone)]
+pub struct DrainNotice {
+    retry_after: Duration,
+    deadline: Time,
+    clock: ClockFn,
+}
+
+impl DrainNotice {
+    /// Creates a notice whose accept-stop `deadline` is read against `clock`.
+    #[must_use]
+    pub fn new(
+        retry_after: Duration,
+        deadline: Time,
+        clock: impl Fn() -> Time + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            retry_after,
+            deadline,
+            clock: Arc::new(clock),
+        }
+    }
+
+    /// Retry hint handed to load balancers.
+    #[must_use]
+    pub const fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Time at which new connections stop being accepted.
+    #[must_use]
+    pub const fn deadline(&self) -> Time {
+        self.deadline
+    }
+
+    /// Time left before new connections stop being accepted.
+    #[must_use]
+    pub fn remaining(&self) -> Duration {
+        let now = (self.clock)();
+        Duration::from_nanos(self.deadline.duration_since(now))
+    }
+
+    fn retry_after_header(&self) -> String {
+        let secs = self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0);
+        secs.max(1).to_string()
+    }
+
+    fn into_probe_response(self) -> Response {
+        let remaining_ms = self.remaining().as_millis();
+        Response::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            b"{\"status\":\"draining\"}".to_vec(),
+        )
+        .header("content-type", "application/json")
+        .header("retry-after", self.retry_after_header())
+        .header("drain-deadline", remaining_ms.to_string())
+    }
+}
+
+impl fmt::Debug for DrainNotice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainNotice")
+            .field("retry_after", &self.retry_after)
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
+
 // ─── HealthCheck ─────────────────────────────────────────────────────────────
 
 /// A named health check function.
@@ -214,6 +296,7 @@ pub struct HealthCheck {
 struct HealthCheckInner {
     checks: Mutex<Vec<(String, CheckFn)>>,
     ready: Arc<Mutex<bool>>,
+    draining: Mutex<Option<DrainNotice>>,
 }
 
 impl fmt::Debug for HealthCheck {
@@ -223,9 +306,11 @@ impl fmt::Debug for HealthCheck {
             checks.iter().map(|(name, _)| name.clone()).collect()
         };
         let ready = *self.inner.ready.lock();
+        let draining = self.inner.draining.lock().is_some();
         f.debug_struct("HealthCheck")
             .field("checks", &names)
             .field("ready", &ready)
+            .field("draining", &draining)
             .finish()
     }
 }
@@ -238,6 +323,7 @@ impl HealthCheck {
             inner: Arc::new(HealthCheckInner {
                 checks: Mutex::new(Vec::new()),
                 ready: Arc::new(Mutex::new(true)),
+                draining: Mutex::new(None),
             }),
         }
     }
@@ -262,9 +348,37 @@ impl HealthCheck {
     }
 
     /// Get the current readiness state.
+    ///
+    /// Returns `false` while a [`DrainNotice`] is installed.
     #[must_use]
     pub fn is_ready(&self) -> bool {
-        *self.inner.ready.lock()
+        *self.inner.ready.lock() && !self.is_draining()
+    }
+
+    /// Flip the readiness gate to "draining".
+    ///
+    /// The readiness endpoint returns 503 with `Retry-After` and
+    /// `Drain-Deadline` headers until [`end_draining`](Self::end_draining)
+    /// is called. Liveness and startup probes are unaffected.
+    pub fn begin_draining(&self, notice: DrainNotice) {
+        *self.inner.draining.lock() = Some(notice);
+    }
+
+    /// Clear the draining state installed by [`begin_draining`](Self::begin_draining).
+    pub fn end_draining(&self) {
+        *self.inner.draining.lock() = None;
+    }
+
+    /// Returns `true` if the readiness gate is draining.
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.inner.draining.lock().is_some()
+    }
+
+    /// Returns the installed drain notice, if any.
+    #[must_use]
+    pub fn drain_notice(&self) -> Option<DrainNotice> {
+        self.inner.draining.lock().clone()
     }
 
     /// Run all health checks and return the aggregated response.
@@ -312,11 +426,15 @@ impl HealthCheck {
     ///
     /// Readiness checks answer "can it serve traffic?" and return
     /// 503 when the service is not ready (e.g., during startup or
-    /// when draining for shutdown).
+    /// when draining for shutdown). While draining, the response also
+    /// carries the [`DrainNotice`] headers.
     #[must_use]
     pub fn readiness_handler(&self) -> FnHandler<impl Fn() -> Response + Send + Sync + 'static> {
         let health = self.clone();
         FnHandler::new(move || {
+            if let Some(notice) = health.drain_notice() {
+                return notice.into_probe_response();
+            }
             if !health.is_ready() {
                 return Response::new(
                     StatusCode::SERVICE_UNAVAILABLE,
@@ -705,6 +823,42 @@ mod tests {
         assert!(body.contains("not_ready"));
     }
 
+    #[test]
+    fn readiness_handler_draining_advertises_deadline() {
+        static NOW: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let hc = HealthCheck::new().check("db", || HealthStatus::Healthy);
+        NOW.store(1_000_000_000, Ordering::SeqCst);
+        hc.begin_draining(DrainNotice::new(
+            Duration::from_millis(2_500),
+            Time::from_secs(6),
+            || Time::from_nanos(NOW.load(Ordering::SeqCst)),
+        ));
+        assert!(!hc.is_ready());
+        assert!(hc.is_draining());
+        let handler = hc.readiness_handler();
+
+        let resp = handler.call_sync(super::super::extract::Request::new("GET", "/readyz"));
+        assert_eq!(resp.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.header_value("Retry-After"), Some("3"));
+        assert_eq!(resp.header_value("Drain-Deadline"), Some("5000"));
+        let body = std::str::from_utf8(&resp.body).unwrap();
+        assert_eq!(body, r#"{"status":"draining"}"#);
+
+        NOW.store(7_000_000_000, Ordering::SeqCst);
+        let resp = handler.call_sync(super::super::extract::Request::new("GET", "/readyz"));
+        assert_eq!(resp.header_value("Drain-Deadline"), Some("0"));
+
+        // Draining must not regress the startup probe.
+        let startup = hc.startup_handler();
+        let resp = startup.call_sync(super::super::extract::Request::new("GET", "/startupz"));
+        assert_eq!(resp.status, StatusCode::OK);
+
+        hc.end_draining();
+        assert!(hc.is_ready());
+        let resp = handler.call_sync(super::super::extract::Request::new("GET", "/readyz"));
+        assert_eq!(resp.status, StatusCode::OK);
+    }
+
     #[test]
     fn startup_handler_started() {
         let hc = HealthCheck::new();
@@ -28,6 +28,7 @@
 //! Extractors pull data from incoming requests:
 //!
 //! - [`Path<T>`]: URL path parameters
+//! - [`PathParams<T>`]: Path parameters in pattern order, via [`FromPathParam`]
 //! - [`Query<T>`]: Query string parameters
 //! - [`Json<T>`]: JSON request body
 //! - [`Header<T>`] / [`TypedHeader<T>`]: Typed request headers
@@ -71,8 +72,8 @@ pub mod websocket;
 
 pub use extract::{
     Accept, Authorization, ContentType, Cookie, CookieJar, Extension, Form, FromHeaderValue,
-    FromRequest, FromRequestParts, Header, HeaderParseError, Json as JsonExtract, Path, Query,
-    State, TypedHeader, UserAgent,
+    FromPathParam, FromPathParams, FromRequest, FromRequestParts, Header, HeaderParseError,
+    Json as JsonExtract, Path, PathParamError, PathParams, Query, State, TypedHeader, UserAgent,
 };
 pub use handler::{
     AsyncCxFnHandler, AsyncCxFnHandler1, AsyncCxFnHandler2, AsyncCxFnHandler3, AsyncCxFnHandler4,
@@ -85,4 +86,4 @@ pub use nextjs_bootstrap::{
     NextjsBootstrapError, NextjsBootstrapSnapshot, NextjsBootstrapState,
 };
 pub use response::{Html, IntoResponse, Json, Redirect, Response, StatusCode};
-pub use router::{MethodRouter, RouteInfo, Router, delete, get, patch, post, put};
+pub use router::{MethodRouter, RouteConflict, RouteInfo, Router, delete, get, patch, post, put};
@@ -18,14 +18,27 @@
 //!     StatusCode::OK
 //! }
 //! ```
+//!
+//! # Streaming
+//!
+//! [`MultipartStream`] parses the same format incrementally over any
+//! [`Body`], yielding one [`MultipartPart`] at a time with a chunked body, so
+//! large uploads never have to be held in memory. Limit violations and
+//! framing errors surface as a typed [`MultipartStreamError`].
 
 use std::collections::HashMap;
+use std::fmt;
+use std::future::poll_fn;
+use std::pin::Pin;
+
+use memchr::memmem;
 
 use super::extract::{
     ExtractionError, FromRequest, Request, header_value_ci, parse_content_length,
 };
 use super::response::StatusCode;
-use crate::bytes::Bytes;
+use crate::bytes::{Buf, Bytes, BytesCursor, BytesMut};
+use crate::http::body::{Body, Full};
 use crate::time::wall_now;
 use crate::types::Time;
 
@@ -299,6 +312,562 @@ impl FromRequest for Multipart {
     }
 }
 
+// ─── Streaming ──────────────────────────────────────────────────────────────
+
+/// Longest run of transport padding (`LWSP`) accepted after a delimiter.
+const MAX_TRANSPORT_PADDING: usize = 64;
+
+/// Error produced by [`MultipartStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultipartStreamError {
+    /// The Content-Type is not `multipart/form-data` or has no valid boundary.
+    InvalidContentType(String),
+    /// The form has more than `max` parts.
+    TooManyParts {
+        /// Configured part limit.
+        max: usize,
+    },
+    /// A part header section is larger than `max` bytes.
+    HeadersTooLarge {
+        /// Configured header section limit.
+        max: usize,
+    },
+    /// A part body is larger than `max` bytes.
+    PartTooLarge {
+        /// Configured per-part limit.
+        max: usize,
+    },
+    /// The request body is larger than `max` bytes.
+    BodyTooLarge {
+        /// Configured total size limit.
+        max: usize,
+    },
+    /// The body ended without any boundary delimiter.
+    MissingInitialBoundary,
+    /// The body ended before the closing `--boundary--` delimiter.
+    MissingTerminalBoundary,
+    /// A part has malformed headers, a nested multipart body, or non-UTF-8
+    /// text where text was requested.
+    MalformedPart(String),
+    /// The underlying request body failed.
+    Body(String),
+}
+
+impl fmt::Display for MultipartStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidContentType(msg) => write!(f, "invalid multipart content type: {msg}"),
+            Self::TooManyParts { max } => write!(f, "too many multipart parts (max {max})"),
+            Self::HeadersTooLarge { max } => {
+                write!(f, "multipart part headers too large (max {max} bytes)")
+            }
+            Self::PartTooLarge { max } => {
+                write!(f, "multipart part body too large (max {max} bytes)")
+            }
+            Self::BodyTooLarge { max } => write!(f, "multipart body too large (max {max} bytes)"),
+            Self::MissingInitialBoundary => f.write_str("multipart body missing initial boundary"),
+            Self::MissingTerminalBoundary => f.write_str("multipart body missing closing boundary"),
+            Self::MalformedPart(msg) => write!(f, "malformed multipart part: {msg}"),
+            Self::Body(msg) => write!(f, "multipart body error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for MultipartStreamError {}
+
+impl From<MultipartStreamError> for ExtractionError {
+    fn from(err: MultipartStreamError) -> Self {
+        let status = match err {
+            MultipartStreamError::InvalidContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            MultipartStreamError::PartTooLarge { .. }
+            | MultipartStreamError::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+#[derive(Debug)]
+enum StreamState {
+    Preamble,
+    Headers,
+    Body,
+    Done,
+    Failed(MultipartStreamError),
+}
+
+/// Outcome of scanning the buffer for the next delimiter line.
+enum DelimiterScan {
+    /// A delimiter line occupies `start..end`; `close` marks `--boundary--`.
+    Found {
+        start: usize,
+        end: usize,
+        close: bool,
+    },
+    /// No complete delimiter yet; the first `safe` bytes are part content.
+    NeedMore { safe: usize },
+}
+
+/// Incremental `multipart/form-data` parser over a streaming [`Body`].
+///
+/// Unlike [`Multipart`], parts are yielded one at a time and their bodies
+/// are read in chunks, so memory stays bounded by the largest body frame
+/// plus the delimiter length regardless of the upload size. The same
+/// [`MultipartLimits`] apply; the timeout fields are not consulted, as the
+/// caller's [`Cx`](crate::cx::Cx) budget governs how long reads may take.
+///
+/// Delimiters must be CRLF-terminated as RFC 2046 requires. A part that is
+/// not fully read is drained when [`next_part`](Self::next_part) is called
+/// again, and still counts toward the size limits.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut form = MultipartStream::from_content_type(body, &content_type, limits)?;
+/// while let Some(mut part) = form.next_part().await? {
+///     if part.filename().is_some() {
+///         while let Some(chunk) = part.chunk().await? {
+///             sink.write_all(&chunk).await?;
+///         }
+///     }
+/// }
+/// ```
+pub struct MultipartStream<B> {
+    body: B,
+    limits: MultipartLimits,
+    /// `\r\n--boundary`; the buffer starts with a virtual CRLF so the first
+    /// delimiter matches like every later one.
+    delimiter: Vec<u8>,
+    buf: BytesMut,
+    state: StreamState,
+    parts: usize,
+    part_len: usize,
+    received: usize,
+    body_done: bool,
+    peak_buffered: usize,
+}
+
+impl<B> fmt::Debug for MultipartStream<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultipartStream")
+            .field("limits", &self.limits)
+            .field("state", &self.state)
+            .field("parts", &self.parts)
+            .field("received", &self.received)
+            .field("buffered", &self.buf.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> MultipartStream<B>
+where
+    B: Body + Send + Unpin,
+    B::Error: fmt::Display,
+{
+    /// Parse `body` using an already-extracted `boundary`.
+    pub fn new(
+        body: B,
+        boundary: &str,
+        limits: MultipartLimits,
+    ) -> Result<Self, MultipartStreamError> {
+        if boundary.is_empty() || boundary.len() > MAX_BOUNDARY_LEN {
+            return Err(MultipartStreamError::InvalidContentType(format!(
+                "boundary must be 1..={MAX_BOUNDARY_LEN} bytes"
+            )));
+        }
+        let mut buf = BytesMut::new();
+        buf.put_slice(b"\r\n");
+        Ok(Self {
+            body,
+            limits,
+            delimiter: format!("\r\n--{boundary}").into_bytes(),
+            buf,
+            state: StreamState::Preamble,
+            parts: 0,
+            part_len: 0,
+            received: 0,
+            body_done: false,
+            peak_buffered: 2,
+        })
+    }
+
+    /// Parse `body` using the boundary from a `multipart/form-data`
+    /// Content-Type header value.
+    pub fn from_content_type(
+        body: B,
+        content_type: &str,
+        limits: MultipartLimits,
+    ) -> Result<Self, MultipartStreamError> {
+        if !is_multipart_form_data(content_type) {
+            return Err(MultipartStreamError::InvalidContentType(format!(
+                "expected multipart/form-data, got: {content_type}"
+            )));
+        }
+        let boundary = extract_boundary(content_type).ok_or_else(|| {
+            MultipartStreamError::InvalidContentType("missing or invalid boundary".to_string())
+        })?;
+        Self::new(body, &boundary, limits)
+    }
+
+    /// Advance to the next part, draining whatever is left of the current
+    /// one. Returns `None` after the closing delimiter.
+    pub async fn next_part(
+        &mut self,
+    ) -> Result<Option<MultipartPart<'_, B>>, MultipartStreamError> {
+        let result = self.advance_part().await;
+        let head = self.record(result)?;
+        Ok(head.map(|head| MultipartPart { stream: self, head }))
+    }
+
+    /// Number of parts started so far.
+    #[must_use]
+    pub fn parts(&self) -> usize {
+        self.parts
+    }
+
+    /// Total request body bytes received so far.
+    #[must_use]
+    pub fn bytes_received(&self) -> usize {
+        self.received
+    }
+
+    /// High-water mark of bytes held in the parse buffer.
+    #[must_use]
+    pub fn peak_buffered(&self) -> usize {
+        self.peak_buffered
+    }
+
+    fn record<T>(
+        &mut self,
+        result: Result<T, MultipartStreamError>,
+    ) -> Result<T, MultipartStreamError> {
+        if let Err(err) = &result {
+            self.state = StreamState::Failed(err.clone());
+        }
+        result
+    }
+
+    async fn advance_part(&mut self) -> Result<Option<PartHead>, MultipartStreamError> {
+        loop {
+            match &self.state {
+                StreamState::Failed(err) => return Err(err.clone()),
+                StreamState::Done => return Ok(None),
+                StreamState::Body => {
+                    while self.read_chunk().await?.is_some() {}
+                }
+                StreamState::Preamble => self.skip_preamble().await?,
+                StreamState::Headers => return self.read_headers().await.map(Some),
+            }
+        }
+    }
+
+    async fn skip_preamble(&mut self) -> Result<(), MultipartStreamError> {
+        loop {
+            match scan_delimiter(&self.buf, &self.delimiter) {
+                DelimiterScan::Found { end, close, .. } => {
+                    self.buf.advance(end);
+                    self.state = if close {
+                        StreamState::Done
+                    } else {
+                        StreamState::Headers
+                    };
+                    return Ok(());
+                }
+                DelimiterScan::NeedMore { safe } => {
+                    self.buf.advance(safe);
+                    if !self.fill().await? {
+                        return Err(MultipartStreamError::MissingInitialBoundary);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn read_headers(&mut self) -> Result<PartHead, MultipartStreamError> {
+        if self.parts >= self.limits.max_parts {
+            return Err(MultipartStreamError::TooManyParts {
+                max: self.limits.max_parts,
+            });
+        }
+        self.parts += 1;
+
+        let max = self.limits.max_part_headers;
+        let header_len = loop {
+            if self.buf.starts_with(b"\r\n") {
+                break 0;
+            }
+            if let Some(pos) = memmem::find(&self.buf, b"\r\n\r\n") {
+                break pos + 2;
+            }
+            if self.buf.len() >= max.saturating_add(4) {
+                return Err(MultipartStreamError::HeadersTooLarge { max });
+            }
+            if !self.fill().await? {
+                return Err(MultipartStreamError::MissingTerminalBoundary);
+            }
+        };
+        if header_len > max.saturating_add(2) {
+            return Err(MultipartStreamError::HeadersTooLarge { max });
+        }
+
+        let headers = parse_part_headers(&self.buf[..header_len])
+            .map_err(|err| MultipartStreamError::MalformedPart(err.message))?;
+        self.buf.advance(header_len + 2);
+
+        let disposition = headers
+            .get("content-disposition")
+            .map_or("", String::as_str);
+        let name = parse_disposition_param(disposition, "name").unwrap_or_default();
+        let filename = parse_disposition_param(disposition, "filename");
+        let content_type = headers.get("content-type").cloned();
+        if content_type.as_deref().is_some_and(is_multipart_media_type) {
+            return Err(MultipartStreamError::MalformedPart(
+                "nested multipart parts are not supported".to_string(),
+            ));
+        }
+
+        self.part_len = 0;
+        self.state = StreamState::Body;
+        Ok(PartHead {
+            name,
+            filename,
+            content_type,
+            headers,
+        })
+    }
+
+    async fn read_chunk(&mut self) -> Result<Option<Bytes>, MultipartStreamError> {
+        loop {
+            let content = match scan_delimiter(&self.buf, &self.delimiter) {
+                DelimiterScan::Found { start: 0, end, close } => {
+                    self.buf.advance(end);
+                    self.state = if close {
+                        StreamState::Done
+                    } else {
+                        StreamState::Headers
+                    };
+                    return Ok(None);
+                }
+                DelimiterScan::Found { start, .. } => start,
+                DelimiterScan::NeedMore { safe } => safe,
+            };
+            if content == 0 {
+                if !self.fill().await? {
+                    return Err(MultipartStreamError::MissingTerminalBoundary);
+                }
+                continue;
+            }
+            self.part_len += content;
+            if self.part_len > self.limits.max_part_body_size {
+                return Err(MultipartStreamError::PartTooLarge {
+                    max: self.limits.max_part_body_size,
+                });
+            }
+            return Ok(Some(self.buf.split_to(content).freeze()));
+        }
+    }
+
+    async fn part_chunk(&mut self) -> Result<Option<Bytes>, MultipartStreamError> {
+        match &self.state {
+            StreamState::Body => {
+                let result = self.read_chunk().await;
+                self.record(result)
+            }
+            StreamState::Failed(err) => Err(err.clone()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Pull the next data frame into the buffer. Returns `false` at EOF.
+    async fn fill(&mut self) -> Result<bool, MultipartStreamError> {
+        while !self.body_done {
+            let frame = poll_fn(|cx| Pin::new(&mut self.body).poll_frame(cx)).await;
+            let mut data = match frame {
+                None => {
+                    self.body_done = true;
+                    break;
+                }
+                Some(Err(err)) => return Err(MultipartStreamError::Body(err.to_string())),
+                Some(Ok(frame)) => match frame.into_data() {
+                    Some(data) if data.has_remaining() => data,
+                    _ => continue,
+                },
+            };
+            let received = self.received.saturating_add(data.remaining());
+            if received > self.limits.max_total_size {
+                return Err(MultipartStreamError::BodyTooLarge {
+                    max: self.limits.max_total_size,
+                });
+            }
+            self.received = received;
+            while data.has_remaining() {
+                let chunk = data.chunk();
+                let len = chunk.len();
+                self.buf.put_slice(chunk);
+                data.advance(len);
+            }
+            self.peak_buffered = self.peak_buffered.max(self.buf.len());
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+impl FromRequest for MultipartStream<Full<BytesCursor>> {
+    fn from_request(req: Request) -> Result<Self, ExtractionError> {
+        let limits = req
+            .extensions
+            .get_typed::<MultipartLimits>()
+            .copied()
+            .unwrap_or_default();
+        check_request_content_length_limit(&req, limits.max_total_size)?;
+        validate_request_content_length(&req)?;
+
+        let content_type = header_value_ci(&req, "content-type")
+            .ok_or_else(|| {
+                ExtractionError::new(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "missing Content-Type header",
+                )
+            })?
+            .to_string();
+        let body = Full::new(BytesCursor::new(req.body));
+        Self::from_content_type(body, &content_type, limits).map_err(Into::into)
+    }
+}
+
+/// Find the first complete delimiter line in `buf`.
+///
+/// A match of `\r\n--boundary` only counts when it is followed by `--` or by
+/// optional padding and CRLF; anything else is part content that merely
+/// starts like the boundary.
+fn scan_delimiter(buf: &[u8], delimiter: &[u8]) -> DelimiterScan {
+    let mut from = 0;
+    while let Some(offset) = memmem::find(&buf[from..], delimiter) {
+        let start = from + offset;
+        let rest = &buf[start + delimiter.len()..];
+        if rest.starts_with(b"--") {
+            return DelimiterScan::Found {
+                start,
+                end: start + delimiter.len() + 2,
+                close: true,
+            };
+        }
+        let padding = rest
+            .iter()
+            .take(MAX_TRANSPORT_PADDING + 1)
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count();
+        if rest.len() < padding + 2 && padding <= MAX_TRANSPORT_PADDING {
+            return DelimiterScan::NeedMore { safe: start };
+        }
+        if padding <= MAX_TRANSPORT_PADDING && rest[padding..].starts_with(b"\r\n") {
+            return DelimiterScan::Found {
+                start,
+                end: start + delimiter.len() + padding + 2,
+                close: false,
+            };
+        }
+        from = start + 1;
+    }
+    // A delimiter may still begin in the last `delimiter.len() - 1` bytes.
+    let tail = buf.len().saturating_sub(from).min(delimiter.len() - 1);
+    DelimiterScan::NeedMore {
+        safe: buf.len() - tail,
+    }
+}
+
+#[derive(Debug)]
+struct PartHead {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    headers: HashMap<String, String>,
+}
+
+/// A part being read from a [`MultipartStream`].
+///
+/// The body is read with [`chunk`](Self::chunk), collected with
+/// [`bytes`](Self::bytes) or [`text`](Self::text), or discarded with
+/// [`skip`](Self::skip). Dropping the part unread is also fine: the stream
+/// drains it before yielding the next part.
+pub struct MultipartPart<'a, B> {
+    stream: &'a mut MultipartStream<B>,
+    head: PartHead,
+}
+
+impl<B> fmt::Debug for MultipartPart<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultipartPart")
+            .field("name", &self.head.name)
+            .field("filename", &self.head.filename)
+            .field("content_type", &self.head.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B> MultipartPart<'_, B>
+where
+    B: Body + Send + Unpin,
+    B::Error: fmt::Display,
+{
+    /// The form field name from `Content-Disposition`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.head.name
+    }
+
+    /// The sanitized filename, if this is a file upload.
+    #[must_use]
+    pub fn filename(&self) -> Option<&str> {
+        self.head.filename.as_deref()
+    }
+
+    /// The content type of this part, if specified.
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.head.content_type.as_deref()
+    }
+
+    /// The part headers, keyed by lowercase name.
+    #[must_use]
+    pub fn headers(&self) -> &HashMap<String, String> {
+        &self.head.headers
+    }
+
+    /// Read the next chunk of the part body, or `None` at its end.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, MultipartStreamError> {
+        self.stream.part_chunk().await
+    }
+
+    /// Read the rest of the part body into memory.
+    pub async fn bytes(&mut self) -> Result<Bytes, MultipartStreamError> {
+        let mut body = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            body.put_slice(&chunk);
+        }
+        Ok(body.freeze())
+    }
+
+    /// Read the rest of the part body as UTF-8 text.
+    pub async fn text(&mut self) -> Result<String, MultipartStreamError> {
+        let body = self.bytes().await?;
+        String::from_utf8(body.to_vec()).map_err(|_| {
+            MultipartStreamError::MalformedPart("part body is not valid UTF-8".to_string())
+        })
+    }
+
+    /// Discard the rest of the part body, returning how many bytes were
+    /// skipped.
+    pub async fn skip(&mut self) -> Result<u64, MultipartStreamError> {
+        let mut skipped = 0u64;
+        while let Some(chunk) = self.chunk().await? {
+            skipped += chunk.len() as u64;
+        }
+        Ok(skipped)
+    }
+}
+
 // ─── Parsing ────────────────────────────────────────────────────────────────
 
 fn check_request_content_length_limit(req: &Request, limit: usize) -> Result<(), ExtractionError> {
@@ -1750,4 +2319,345 @@ mod tests {
         assert_eq!(err.status, StatusCode::REQUEST_TIMEOUT);
         assert!(err.message.contains("multipart parsing idle"));
     }
+
+    // ================================================================
+    // Streaming parser
+    // ================================================================
+
+    use crate::http::body::Frame;
+    use crate::http::multipart::MultipartBody;
+    use crate::io::{AsyncRead, ReadBuf};
+    use std::convert::Infallible;
+    use std::task::{Context, Poll, Waker};
+
+    /// Body that yields `data` in frames of at most `step` bytes.
+    struct Trickle {
+        data: Bytes,
+        step: usize,
+    }
+
+    impl Body for Trickle {
+        type Data = BytesCursor;
+        type Error = Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<BytesCursor>, Infallible>>> {
+            if self.data.is_empty() {
+                return Poll::Ready(None);
+            }
+            let step = self.step.min(self.data.len());
+            let chunk = self.data.slice(..step);
+            self.data = self.data.slice(step..);
+            Poll::Ready(Some(Ok(Frame::Data(BytesCursor::new(chunk)))))
+        }
+    }
+
+    /// Reader producing `remaining` bytes of a position-derived pattern.
+    struct PatternReader {
+        offset: u64,
+        remaining: u64,
+    }
+
+    impl AsyncRead for PatternReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let n = (buf.remaining() as u64).min(self.remaining) as usize;
+            let chunk: Vec<u8> = (0..n as u64)
+                .map(|i| pattern_byte(self.offset + i))
+                .collect();
+            buf.put_slice(&chunk);
+            self.offset += n as u64;
+            self.remaining -= n as u64;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn pattern_byte(offset: u64) -> u8 {
+        (offset % 251) as u8
+    }
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut f = std::pin::pin!(f);
+        loop {
+            if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn trickle(raw: &[u8], step: usize) -> MultipartStream<Trickle> {
+        let body = Trickle {
+            data: Bytes::copy_from_slice(raw),
+            step,
+        };
+        MultipartStream::new(body, "BOUNDARY", MultipartLimits::new()).unwrap()
+    }
+
+    fn full(raw: &[u8], limits: MultipartLimits) -> MultipartStream<Full<BytesCursor>> {
+        let body = Full::new(BytesCursor::new(Bytes::copy_from_slice(raw)));
+        MultipartStream::new(body, "BOUNDARY", limits).unwrap()
+    }
+
+    async fn collect_parts<B>(
+        stream: &mut MultipartStream<B>,
+    ) -> Result<Vec<(String, Vec<u8>)>, MultipartStreamError>
+    where
+        B: Body + Send + Unpin,
+        B::Error: fmt::Display,
+    {
+        let mut parts = Vec::new();
+        while let Some(mut part) = stream.next_part().await? {
+            let name = part.name().to_string();
+            let body = part.bytes().await?;
+            parts.push((name, body.to_vec()));
+        }
+        Ok(parts)
+    }
+
+    const TWO_PARTS: &[u8] = b"--BOUNDARY\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\nhello\r\n\
+        --BOUNDARY\r\n\
+        Content-Disposition: form-data; name=\"b\"\r\n\r\nworld\r\n\
+        --BOUNDARY--\r\n";
+
+    #[test]
+    fn stream_round_trips_client_body_with_mixed_parts() {
+        let upload: Vec<u8> = (0..200_000u64).map(pattern_byte).collect();
+        let body = MultipartBody::builder()
+            .text("title", "quarterly report")
+            .bytes("notes", "notes.txt", "text/plain", b"line one\r\nline two".to_vec())
+            .reader(
+                "upload",
+                "../../data.bin",
+                "application/octet-stream",
+                PatternReader {
+                    offset: 0,
+                    remaining: upload.len() as u64,
+                },
+                None,
+            )
+            .text("empty", "")
+            .build()
+            .unwrap();
+        let content_type = body.content_type_header();
+        let mut stream =
+            MultipartStream::from_content_type(body, &content_type, MultipartLimits::new())
+                .unwrap();
+
+        block_on(async {
+            let mut part = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(part.name(), "title");
+            assert_eq!(part.filename(), None);
+            assert_eq!(part.text().await.unwrap(), "quarterly report");
+
+            let mut part = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(part.name(), "notes");
+            assert_eq!(part.filename(), Some("notes.txt"));
+            assert_eq!(part.content_type(), Some("text/plain"));
+            assert_eq!(&part.bytes().await.unwrap()[..], b"line one\r\nline two");
+
+            let mut part = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(part.name(), "upload");
+            assert_eq!(part.filename(), Some("data.bin"));
+            assert_eq!(part.content_type(), Some("application/octet-stream"));
+            let mut received = Vec::new();
+            while let Some(chunk) = part.chunk().await.unwrap() {
+                received.extend_from_slice(&chunk);
+            }
+            assert_eq!(received, upload);
+
+            let mut part = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(part.name(), "empty");
+            assert_eq!(part.text().await.unwrap(), "");
+
+            assert!(stream.next_part().await.unwrap().is_none());
+            assert!(stream.next_part().await.unwrap().is_none());
+        });
+        assert_eq!(stream.parts(), 4);
+    }
+
+    #[test]
+    fn stream_parses_byte_at_a_time_frames() {
+        let mut stream = trickle(TWO_PARTS, 1);
+        let parts = block_on(collect_parts(&mut stream)).unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                ("a".to_string(), b"hello".to_vec()),
+                ("b".to_string(), b"world".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_part_count_limit_is_exact() {
+        let mut ok = full(TWO_PARTS, MultipartLimits::new().max_parts(2));
+        assert_eq!(block_on(collect_parts(&mut ok)).unwrap().len(), 2);
+
+        let mut over = full(TWO_PARTS, MultipartLimits::new().max_parts(1));
+        let err = block_on(collect_parts(&mut over)).unwrap_err();
+        assert_eq!(err, MultipartStreamError::TooManyParts { max: 1 });
+    }
+
+    #[test]
+    fn stream_part_size_limit_is_exact() {
+        let mut ok = full(TWO_PARTS, MultipartLimits::new().max_part_body_size(5));
+        assert_eq!(block_on(collect_parts(&mut ok)).unwrap().len(), 2);
+
+        let mut over = full(TWO_PARTS, MultipartLimits::new().max_part_body_size(4));
+        let err = block_on(collect_parts(&mut over)).unwrap_err();
+        assert_eq!(err, MultipartStreamError::PartTooLarge { max: 4 });
+        assert_eq!(
+            ExtractionError::from(err).status,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[test]
+    fn stream_total_size_limit_is_exact() {
+        let limits = MultipartLimits::new().max_total_size(TWO_PARTS.len());
+        let mut ok = full(TWO_PARTS, limits);
+        assert_eq!(block_on(collect_parts(&mut ok)).unwrap().len(), 2);
+
+        let limits = MultipartLimits::new().max_total_size(TWO_PARTS.len() - 1);
+        let mut over = full(TWO_PARTS, limits);
+        let err = block_on(collect_parts(&mut over)).unwrap_err();
+        assert_eq!(
+            err,
+            MultipartStreamError::BodyTooLarge {
+                max: TWO_PARTS.len() - 1
+            }
+        );
+    }
+
+    #[test]
+    fn stream_header_size_limit_is_exact() {
+        let header_len = "Content-Disposition: form-data; name=\"a\"".len();
+        let mut ok = trickle(TWO_PARTS, 3);
+        ok.limits = MultipartLimits::new().max_part_headers(header_len);
+        assert_eq!(block_on(collect_parts(&mut ok)).unwrap().len(), 2);
+
+        let mut over = trickle(TWO_PARTS, 3);
+        over.limits = MultipartLimits::new().max_part_headers(header_len - 1);
+        let err = block_on(collect_parts(&mut over)).unwrap_err();
+        assert_eq!(
+            err,
+            MultipartStreamError::HeadersTooLarge {
+                max: header_len - 1
+            }
+        );
+    }
+
+    #[test]
+    fn stream_reports_missing_or_malformed_terminator() {
+        let truncated = &TWO_PARTS[..TWO_PARTS.len() - "--BOUNDARY--\r\n".len()];
+        let mut stream = trickle(truncated, 5);
+        let err = block_on(collect_parts(&mut stream)).unwrap_err();
+        assert_eq!(err, MultipartStreamError::MissingTerminalBoundary);
+
+        let malformed = b"--BOUNDARY\r\n\r\nvalue\r\n--BOUNDARY-";
+        let mut stream = trickle(malformed, 64);
+        let err = block_on(collect_parts(&mut stream)).unwrap_err();
+        assert_eq!(err, MultipartStreamError::MissingTerminalBoundary);
+        // The failure is sticky.
+        let again = block_on(stream.next_part()).map(|part| part.is_some());
+        assert_eq!(again, Err(MultipartStreamError::MissingTerminalBoundary));
+
+        let mut stream = trickle(b"no delimiter here", 4);
+        let err = block_on(collect_parts(&mut stream)).unwrap_err();
+        assert_eq!(err, MultipartStreamError::MissingInitialBoundary);
+    }
+
+    #[test]
+    fn stream_keeps_boundary_lookalikes_in_content() {
+        let content: &[u8] = b"x\r\n--BOUNDARYX\r\n--BOUNDARY \tz\r\n--BOUNDAR\r\n-";
+        let mut raw = b"preamble\r\n--BOUNDARY  \r\n\r\n".to_vec();
+        raw.extend_from_slice(content);
+        raw.extend_from_slice(b"\r\n--BOUNDARY--\r\nepilogue");
+        for step in [1, 2, 3, 11, raw.len()] {
+            let mut stream = trickle(&raw, step);
+            let parts = block_on(collect_parts(&mut stream)).unwrap();
+            let expected = vec![(String::new(), content.to_vec())];
+            assert_eq!(parts, expected, "step {step}");
+        }
+    }
+
+    #[test]
+    fn stream_skips_unread_parts() {
+        let mut stream = trickle(TWO_PARTS, 2);
+        block_on(async {
+            let first = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(first.name(), "a");
+            drop(first);
+            let mut second = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(second.name(), "b");
+            assert_eq!(second.skip().await.unwrap(), 5);
+            assert!(second.chunk().await.unwrap().is_none());
+            assert!(stream.next_part().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn stream_extractor_checks_content_type() {
+        let req = multipart_request(Bytes::copy_from_slice(TWO_PARTS));
+        let mut stream = MultipartStream::from_request(req).unwrap();
+        assert_eq!(block_on(collect_parts(&mut stream)).unwrap().len(), 2);
+
+        let req = Request::new("POST", "/upload")
+            .with_header("content-type", "application/json")
+            .with_body(Bytes::copy_from_slice(TWO_PARTS));
+        let err = MultipartStream::from_request(req).unwrap_err();
+        assert_eq!(err.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn stream_large_upload_under_lab_keeps_memory_bounded() {
+        use crate::conformance::{ConformanceTarget, LabRuntimeTarget, TestConfig};
+
+        const UPLOAD_LEN: u64 = 16 * 1024 * 1024;
+        let mut runtime = LabRuntimeTarget::create_runtime(TestConfig::new().with_seed(0x5EED));
+        let (received, peak) = LabRuntimeTarget::block_on(&mut runtime, async move {
+            let body = MultipartBody::builder()
+                .text("kind", "bulk")
+                .reader(
+                    "blob",
+                    "blob.bin",
+                    "application/octet-stream",
+                    PatternReader {
+                        offset: 0,
+                        remaining: UPLOAD_LEN,
+                    },
+                    Some(UPLOAD_LEN),
+                )
+                .build()
+                .unwrap();
+            let content_type = body.content_type_header();
+            let limits = MultipartLimits::new()
+                .max_total_size(32 * 1024 * 1024)
+                .max_part_body_size(UPLOAD_LEN as usize);
+            let mut stream = MultipartStream::from_content_type(body, &content_type, limits)
+                .unwrap();
+
+            let mut kind = stream.next_part().await.unwrap().unwrap();
+            assert_eq!(kind.text().await.unwrap(), "bulk");
+            let mut blob = stream.next_part().await.unwrap().unwrap();
+            let mut received = 0u64;
+            while let Some(chunk) = blob.chunk().await.unwrap() {
+                for (i, byte) in chunk.iter().enumerate() {
+                    assert_eq!(*byte, pattern_byte(received + i as u64));
+                }
+                received += chunk.len() as u64;
+            }
+            assert!(stream.next_part().await.unwrap().is_none());
+            (received, stream.peak_buffered())
+        });
+        assert_eq!(received, UPLOAD_LEN);
+        assert!(peak <= 64 * 1024 + 256, "peak buffered {peak} bytes");
+    }
 }
@@ -771,6 +771,7 @@ impl ServerRequestRegion {
                 cx.set_trace_buffer(trace);
             }
             cx.runtime_mask = ambient.runtime_mask;
+            cx.capability_restriction = ambient.task_capability_restriction();
             return Some(cx);
         }
         // Last resort, test builds only: a detached request context so
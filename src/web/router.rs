@@ -2,24 +2,53 @@
 //!
 //! # Routing
 //!
-//! Routes map URL patterns to handlers. Path parameters are denoted with `:param`.
+//! Routes map URL patterns to handlers. Path parameters are denoted with `:param`,
+//! a trailing catch-all with `*` (captured as `"*"`) or `*name`.
 //!
 //! ```ignore
 //! let app = Router::new()
 //!     .route("/", get(index))
 //!     .route("/users", get(list_users).post(create_user))
 //!     .route("/users/:id", get(get_user).delete(delete_user))
+//!     .route("/files/*path", get(serve_file))
 //!     .nest("/api/v1", api_v1_routes());
 //! ```
+//!
+//! # Path decoding
+//!
+//! The request path is split on `/` first and each segment is percent-decoded
+//! second, so `%2F` never creates a segment boundary. Literal pattern segments
+//! match the raw, still-encoded segment byte for byte — `/%61dmin` does not
+//! reach an `/admin` route, so path filters in front of the router see the
+//! same bytes the router routes on. Captured parameters are decoded; a `%`
+//! not followed by two hex digits, or an escape sequence that does not decode
+//! to UTF-8, answers `400 Bad Request` before any route is consulted.
+//!
+//! # Trailing slashes
+//!
+//! Trailing slashes are insignificant on both sides: `/users/` matches
+//! `/users`, and registering both `/users` and `/users/` for one method is a
+//! conflict. Empty interior segments (`/users//42`) never match a route.
+//!
+//! # Conflicts
+//!
+//! Overlapping routes are ordered by specificity (see [`Router`]). Two routes
+//! that the ordering cannot separate — equally specific patterns that match a
+//! common path, such as `/users/:id` and `/users/:name` — are rejected at
+//! registration when they share a method, with both registration sites in the
+//! [`RouteConflict`].
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
+use std::panic::Location;
 
 use smallvec::SmallVec;
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use super::extract::{Extensions, Request};
+use super::extract::{Extensions, ExtractionError, PathParamNames, Request};
 use super::handler::Handler;
 use super::middleware::{
     RequestLogSink, RequestTracePolicy, resolve_trace_id, trace_request, wall_clock_now,
@@ -61,6 +90,43 @@ pub struct RouteInfo {
     pub mount_prefix: Option<String>,
 }
 
+/// Two registrations the router cannot order, returned by
+/// [`Router::try_route`] and [`Router::try_nest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConflict {
+    /// Method both routes handle; `None` for two routers nested at one prefix.
+    pub method: Option<String>,
+    /// Pattern (or nest prefix) registered first.
+    pub existing: String,
+    /// Where the first registration was made.
+    pub existing_site: &'static Location<'static>,
+    /// Pattern (or nest prefix) that was rejected.
+    pub conflicting: String,
+    /// Where the rejected registration was made.
+    pub conflicting_site: &'static Location<'static>,
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.method {
+            Some(method) => write!(
+                f,
+                "route conflict: {method} {} (registered at {}) matches the same paths as \
+                 {method} {} (registered at {})",
+                self.conflicting, self.conflicting_site, self.existing, self.existing_site
+            ),
+            None => write!(
+                f,
+                "route conflict: router nested at {} (registered at {}) is shadowed by the \
+                 router nested at {} (registered at {})",
+                self.conflicting, self.conflicting_site, self.existing, self.existing_site
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RouteConflict {}
+
 // ─── MethodRouter ────────────────────────────────────────────────────────────
 
 /// A set of handlers for different HTTP methods on a single route.
@@ -151,6 +217,11 @@ impl MethodRouter {
         sorted_methods(self.handlers.keys().map(String::as_str))
     }
 
+    /// Whether a handler is registered for `method` (case-insensitive).
+    fn handles(&self, method: &str) -> bool {
+        self.handlers.contains_key(method) || self.handlers.contains_key(&method.to_uppercase())
+    }
+
     fn allow_header(&self) -> String {
         self.methods().join(", ")
     }
@@ -192,6 +263,11 @@ struct MethodNotAllowedHandler {
     allow: String,
 }
 
+/// `Allow` value for a 405 answered on behalf of several equally specific
+/// routes, none of which handles the request method.
+#[derive(Debug, Clone)]
+struct TiedAllow(String);
+
 impl MethodNotAllowedHandler {
     fn new(allow: String) -> Self {
         Self { allow }
@@ -202,9 +278,12 @@ impl Handler for MethodNotAllowedHandler {
     fn call(
         &self,
         _cx: &Cx,
-        _req: Request,
+        req: Request,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Response> + Send + '_>> {
-        let allow = self.allow.clone();
+        let allow = req
+            .extensions
+            .get_typed::<TiedAllow>()
+            .map_or_else(|| self.allow.clone(), |tied| tied.0.clone());
         Box::pin(async move {
             let mut resp = StatusCode::METHOD_NOT_ALLOWED.into_response();
             if !allow.is_empty() {
@@ -274,10 +353,11 @@ pub fn patch(handler: impl Handler) -> MethodRouter {
 #[derive(Debug, Clone)]
 struct RoutePattern {
     /// The original pattern string (e.g., "/users/:id/posts/:post_id").
-    #[allow(dead_code)] // retained for debug diagnostics
     raw: String,
     /// Segments: either literal strings or parameter names.
     segments: Vec<Segment>,
+    /// Capture names in pattern order, for positional extraction.
+    param_names: Arc<[String]>,
 }
 
 #[derive(Debug, Clone)]
@@ -298,63 +378,58 @@ struct RouteSpecificity {
 enum Segment {
     Literal(String),
     Param(String),
-    Wildcard,
+    /// Catch-all; `None` captures under `"*"`.
+    Wildcard(Option<String>),
 }
 
 impl RoutePattern {
     /// Parse a route pattern string.
     fn parse(pattern: &str) -> Self {
-        let segments = pattern
+        let segments: Vec<Segment> = pattern
             .split('/')
             .filter(|s| !s.is_empty())
             .map(|s| {
-                s.strip_prefix(':').map_or_else(
-                    || {
-                        if s == "*" {
-                            Segment::Wildcard
-                        } else {
-                            Segment::Literal(s.to_string())
-                        }
-                    },
-                    |param| Segment::Param(param.to_string()),
-                )
+                if let Some(param) = s.strip_prefix(':') {
+                    Segment::Param(param.to_string())
+                } else if let Some(name) = s.strip_prefix('*') {
+                    Segment::Wildcard((!name.is_empty()).then(|| name.to_string()))
+                } else {
+                    Segment::Literal(s.to_string())
+                }
+            })
+            .collect();
+        let param_names = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Literal(_) => None,
+                Segment::Param(name) | Segment::Wildcard(Some(name)) => Some(name.clone()),
+                Segment::Wildcard(None) => Some("*".to_string()),
             })
             .collect();
 
         Self {
             raw: pattern.to_string(),
             segments,
+            param_names,
         }
     }
 
     /// Try to match a path against this pattern, extracting parameters.
+    #[cfg(test)]
     fn matches(&self, path: &str) -> Option<RouteMatch> {
-        // br-asupersync-router-empty-seg: reject paths containing
-        // empty segments ("//"). Per RFC 3986, an empty segment is
-        // semantically distinct from no segment, and silently
-        // collapsing it would let "/users//foo" match a "/users/:id"
-        // route as :id="foo" (or :id="" under a different
-        // implementation, which is even worse). Both options leak
-        // path-confusion attacks: an attacker could craft a URL
-        // that bypasses path-prefix-based access controls (e.g.,
-        // "/api//admin" might evade a filter that expects
-        // "/api/admin" while still routing to the admin handler).
-        // strip_prefix() in this same module already rejects empty
-        // segments at mount boundaries (see
-        // strip_prefix_rejects_empty_segment_at_mount_boundary
-        // test); the matcher must agree to keep the routing
-        // surface consistent.
         if path.contains("//") {
             return None;
         }
-        let path_segments: SmallVec<[&str; 8]> =
-            path.split('/').filter(|s| !s.is_empty()).collect();
+        self.matches_segments(&split_path(path).ok()?)
+    }
 
+    /// Try to match split request segments, extracting decoded parameters.
+    fn matches_segments(&self, path_segments: &[PathSegment<'_>]) -> Option<RouteMatch> {
         // Check for wildcard at the end.
         let has_wildcard = self
             .segments
             .last()
-            .is_some_and(|s| matches!(s, Segment::Wildcard));
+            .is_some_and(|s| matches!(s, Segment::Wildcard(_)));
 
         if has_wildcard {
             if path_segments.len() < self.segments.len() - 1 {
@@ -369,21 +444,25 @@ impl RoutePattern {
         for (i, segment) in self.segments.iter().enumerate() {
             match segment {
                 Segment::Literal(lit) => {
-                    if path_segments.get(i) != Some(&lit.as_str()) {
+                    if path_segments.get(i).map(|s| s.raw) != Some(lit.as_str()) {
                         return None;
                     }
                 }
                 Segment::Param(name) => {
-                    if let Some(&value) = path_segments.get(i) {
-                        params.insert(name.clone(), value.to_string());
+                    if let Some(value) = path_segments.get(i) {
+                        params.insert(name.clone(), value.decoded.to_string());
                     } else {
                         return None;
                     }
                 }
-                Segment::Wildcard => {
+                Segment::Wildcard(name) => {
                     // Wildcard matches the rest of the path.
-                    let rest = path_segments[i..].join("/");
-                    params.insert("*".to_string(), rest);
+                    let rest = path_segments[i..]
+                        .iter()
+                        .map(|s| s.decoded.as_ref())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    params.insert(name.clone().unwrap_or_else(|| "*".to_string()), rest);
                     return Some(RouteMatch {
                         params,
                         specificity: self.specificity(),
@@ -407,7 +486,7 @@ impl RoutePattern {
             match segment {
                 Segment::Literal(_) => literal_segments += 1,
                 Segment::Param(_) => param_segments += 1,
-                Segment::Wildcard => exact_path = false,
+                Segment::Wildcard(_) => exact_path = false,
             }
         }
 
@@ -418,6 +497,114 @@ impl RoutePattern {
             total_segments: self.segments.len(),
         }
     }
+
+    /// Whether some request path matches both patterns.
+    ///
+    /// A wildcard matches any remainder, including an empty one, so the
+    /// first wildcard on either side ends the comparison.
+    fn overlaps(&self, other: &Self) -> bool {
+        let mut left = self.segments.iter();
+        let mut right = other.segments.iter();
+        loop {
+            match (left.next(), right.next()) {
+                (Some(Segment::Wildcard(_)), _) | (_, Some(Segment::Wildcard(_))) => return true,
+                (None, None) => return true,
+                (None, Some(_)) | (Some(_), None) => return false,
+                (Some(Segment::Literal(l)), Some(Segment::Literal(r))) if l != r => return false,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The first method both routes handle, if the specificity ordering cannot
+/// choose between them for some path.
+fn ambiguous_method(
+    existing: (&RoutePattern, &MethodRouter),
+    candidate: (&RoutePattern, &MethodRouter),
+) -> Option<String> {
+    if existing.0.specificity() != candidate.0.specificity() || !existing.0.overlaps(candidate.0) {
+        return None;
+    }
+    candidate
+        .1
+        .methods()
+        .into_iter()
+        .find(|method| existing.1.handlers.contains_key(method))
+}
+
+// ─── Path Decoding ───────────────────────────────────────────────────────────
+
+/// One non-empty request path segment.
+#[derive(Debug)]
+struct PathSegment<'a> {
+    /// Bytes as received; literal pattern segments compare against these.
+    raw: &'a str,
+    /// Percent-decoded value; parameters capture this.
+    decoded: Cow<'a, str>,
+}
+
+/// Why a request path could not be decoded for routing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathDecodeError {
+    /// A `%` not followed by two hex digits.
+    MalformedEscape,
+    /// Escapes that decode to bytes which are not UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for PathDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedEscape => f.write_str("malformed percent-encoding in request path"),
+            Self::InvalidUtf8 => f.write_str("percent-decoded request path is not valid UTF-8"),
+        }
+    }
+}
+
+/// Split a request path into its non-empty segments, decoding each one.
+///
+/// Splitting happens before decoding, so `%2F` stays inside its segment.
+fn split_path(path: &str) -> Result<SmallVec<[PathSegment<'_>; 8]>, PathDecodeError> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|raw| decode_segment(raw).map(|decoded| PathSegment { raw, decoded }))
+        .collect()
+}
+
+fn decode_segment(segment: &str) -> Result<Cow<'_, str>, PathDecodeError> {
+    if !segment.contains('%') {
+        return Ok(Cow::Borrowed(segment));
+    }
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hi = bytes.get(i + 1).copied().and_then(hex_digit);
+            let lo = bytes.get(i + 2).copied().and_then(hex_digit);
+            let (Some(hi), Some(lo)) = (hi, lo) else {
+                return Err(PathDecodeError::MalformedEscape);
+            };
+            out.push((hi << 4) | lo);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out)
+        .map(Cow::Owned)
+        .map_err(|_| PathDecodeError::InvalidUtf8)
+}
+
+fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
 }
 
 // ─── Router ──────────────────────────────────────────────────────────────────
@@ -425,8 +612,10 @@ impl RoutePattern {
 /// HTTP request router.
 ///
 /// Routes are matched by specificity: exact paths beat wildcard routes, literal
-/// segments beat parameter segments, and registration order only breaks ties
-/// between equally specific patterns.
+/// segments beat parameter segments, and more segments beat fewer. Equally
+/// specific routes may overlap only when they handle disjoint methods; the
+/// route handling the request method answers, and a method none of them
+/// handles gets a `405` whose `Allow` header lists all of theirs.
 ///
 /// # Path Parameters
 ///
@@ -450,8 +639,8 @@ impl RoutePattern {
 ///     .nest("/api/v1", api);
 /// ```
 pub struct Router {
-    routes: Vec<(RoutePattern, MethodRouter)>,
-    nested: Vec<(String, Self)>,
+    routes: Vec<(RoutePattern, MethodRouter, &'static Location<'static>)>,
+    nested: Vec<(String, Self, &'static Location<'static>)>,
     fallback: Option<Box<dyn Handler>>,
     extensions: Extensions,
     default_trace: Option<DefaultTrace>,
@@ -503,18 +692,94 @@ impl Router {
     }
 
     /// Register a route with the given pattern and method router.
+    ///
+    /// # Panics
+    ///
+    /// Panics with both registration sites when the route conflicts with an
+    /// earlier one; see [`Router::try_route`].
     #[must_use]
-    pub fn route(mut self, pattern: &str, method_router: MethodRouter) -> Self {
-        self.routes
-            .push((RoutePattern::parse(pattern), method_router));
-        self
+    #[track_caller]
+    pub fn route(self, pattern: &str, method_router: MethodRouter) -> Self {
+        match self.try_route(pattern, method_router) {
+            Ok(router) => router,
+            Err(conflict) => panic!("{conflict}"),
+        }
+    }
+
+    /// Register a route, rejecting it if it conflicts with an earlier one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RouteConflict`] when an earlier route is equally specific,
+    /// matches a common path, and handles one of the same methods: the
+    /// router would have no way to pick between them.
+    #[track_caller]
+    pub fn try_route(
+        mut self,
+        pattern: &str,
+        method_router: MethodRouter,
+    ) -> Result<Self, RouteConflict> {
+        let site = Location::caller();
+        let pattern = RoutePattern::parse(pattern);
+        for (existing, existing_methods, existing_site) in &self.routes {
+            if let Some(method) =
+                ambiguous_method((existing, existing_methods), (&pattern, &method_router))
+            {
+                return Err(RouteConflict {
+                    method: Some(method),
+                    existing: existing.raw.clone(),
+                    existing_site: *existing_site,
+                    conflicting: pattern.raw,
+                    conflicting_site: site,
+                });
+            }
+        }
+        self.routes.push((pattern, method_router, site));
+        Ok(self)
     }
 
     /// Mount a sub-router at the given prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics with both registration sites when a router is already mounted
+    /// at the same prefix; see [`Router::try_nest`].
     #[must_use]
-    pub fn nest(mut self, prefix: &str, router: Self) -> Self {
-        self.nested.push((prefix.to_string(), router));
-        self
+    #[track_caller]
+    pub fn nest(self, prefix: &str, router: Self) -> Self {
+        match self.try_nest(prefix, router) {
+            Ok(router) => router,
+            Err(conflict) => panic!("{conflict}"),
+        }
+    }
+
+    /// Mount a sub-router, rejecting a second router at the same prefix.
+    ///
+    /// Requests go to the router with the longest matching prefix, so a
+    /// second router at an equal prefix would never be reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RouteConflict`] without a method when a router is already
+    /// mounted at the same prefix.
+    #[track_caller]
+    pub fn try_nest(mut self, prefix: &str, router: Self) -> Result<Self, RouteConflict> {
+        let site = Location::caller();
+        if let Some((existing, _, existing_site)) = self
+            .nested
+            .iter()
+            .find(|(existing, _, _)| same_mount_prefix(existing, prefix))
+        {
+            return Err(RouteConflict {
+                method: None,
+                existing: existing.clone(),
+                existing_site: *existing_site,
+                conflicting: prefix.to_string(),
+                conflicting_site: site,
+            });
+        }
+        self.nested.push((prefix.to_string(), router, site));
+        Ok(self)
     }
 
     /// Set a fallback handler for unmatched routes.
@@ -580,13 +845,13 @@ impl Router {
 
     /// Apply a handler wrapper to all routes, the fallback, and nested routers.
     fn apply_wrap(&mut self, wrap: &dyn Fn(Box<dyn Handler>) -> Box<dyn Handler>) {
-        for (_, method_router) in &mut self.routes {
+        for (_, method_router, _) in &mut self.routes {
             method_router.map_handlers(wrap);
         }
         if let Some(fallback) = self.fallback.take() {
             self.fallback = Some(wrap(fallback));
         }
-        for (_, nested) in &mut self.nested {
+        for (_, nested, _) in &mut self.nested {
             nested.apply_wrap(wrap);
         }
     }
@@ -706,31 +971,34 @@ impl Router {
     async fn handle_inner(&self, cx: &Cx, mut req: Request) -> Response {
         req.extensions.extend_from(&self.extensions);
 
-        // Pick the most specific top-level route. First-registered only wins
-        // among equal-specificity routes; broad wildcard routes must not shadow
-        // narrower protected paths.
-        let mut best_route: Option<(RouteSpecificity, &MethodRouter, HashMap<String, String>)> =
-            None;
-        for (pattern, method_router) in &self.routes {
-            if let Some(route_match) = pattern.matches(&req.path) {
-                match &best_route {
-                    Some((best_specificity, _, _))
-                        if *best_specificity >= route_match.specificity => {}
-                    _ => {
-                        best_route =
-                            Some((route_match.specificity, method_router, route_match.params));
-                    }
-                }
+        let mut tied = match split_path(&req.path) {
+            Ok(segments) => self.most_specific_routes(&req.path, &segments),
+            Err(err) => return ExtractionError::bad_request(err.to_string()).into_response(),
+        };
+        if !tied.is_empty() {
+            // Registration guarantees equally specific overlapping routes
+            // handle disjoint methods, so at most one of them handles this one.
+            let handling = tied
+                .iter()
+                .position(|(_, method_router, _)| method_router.handles(&req.method));
+            if handling.is_none() && tied.len() > 1 {
+                let methods = tied
+                    .iter()
+                    .flat_map(|(_, method_router, _)| method_router.handlers.keys());
+                let mut allow = sorted_methods(methods.map(String::as_str));
+                allow.dedup();
+                req.extensions.insert_typed(TiedAllow(allow.join(", ")));
             }
-        }
-        if let Some((_, method_router, params)) = best_route {
-            req.path_params = params;
+            let (pattern, method_router, route_match) = tied.swap_remove(handling.unwrap_or(0));
+            req.path_params = route_match.params;
+            req.extensions
+                .insert_typed(PathParamNames(Arc::clone(&pattern.param_names)));
             return method_router.dispatch(cx, req).await;
         }
 
         // Check nested routers.
         let mut best_nested_match: Option<(usize, &Self, String)> = None;
-        for (prefix, router) in &self.nested {
+        for (prefix, router, _) in &self.nested {
             if let Some(sub_path) = strip_prefix(&req.path, prefix) {
                 let normalized_len = prefix.trim_end_matches('/').len();
                 match &best_nested_match {
@@ -754,6 +1022,53 @@ impl Router {
         StatusCode::NOT_FOUND.into_response()
     }
 
+    /// The top-level routes matching `path`, all of the highest specificity.
+    ///
+    /// Broad wildcard routes must not shadow narrower protected paths, so
+    /// registration order never decides between routes of different
+    /// specificity.
+    fn most_specific_routes(
+        &self,
+        path: &str,
+        segments: &[PathSegment<'_>],
+    ) -> SmallVec<[(&RoutePattern, &MethodRouter, RouteMatch); 2]> {
+        let mut tied: SmallVec<[(&RoutePattern, &MethodRouter, RouteMatch); 2]> = SmallVec::new();
+        // br-asupersync-router-empty-seg: reject paths containing
+        // empty segments ("//"). Per RFC 3986, an empty segment is
+        // semantically distinct from no segment, and silently
+        // collapsing it would let "/users//foo" match a "/users/:id"
+        // route as :id="foo" (or :id="" under a different
+        // implementation, which is even worse). Both options leak
+        // path-confusion attacks: an attacker could craft a URL
+        // that bypasses path-prefix-based access controls (e.g.,
+        // "/api//admin" might evade a filter that expects
+        // "/api/admin" while still routing to the admin handler).
+        // strip_prefix() in this same module already rejects empty
+        // segments at mount boundaries (see
+        // strip_prefix_rejects_empty_segment_at_mount_boundary
+        // test); the matcher must agree to keep the routing
+        // surface consistent.
+        if path.contains("//") {
+            return tied;
+        }
+        for (pattern, method_router, _) in &self.routes {
+            let Some(route_match) = pattern.matches_segments(segments) else {
+                continue;
+            };
+            match tied.first().map(|(_, _, best)| best.specificity) {
+                Some(best) if best > route_match.specificity => {}
+                Some(best) if best == route_match.specificity => {
+                    tied.push((pattern, method_router, route_match));
+                }
+                _ => {
+                    tied.clear();
+                    tied.push((pattern, method_router, route_match));
+                }
+            }
+        }
+        tied
+    }
+
     /// Return the number of registered routes (not counting nested).
     #[must_use]
     pub fn route_count(&self) -> usize {
@@ -796,12 +1111,12 @@ impl Router {
         mount_prefix: Option<&str>,
         entries: &mut Vec<RouteInfo>,
     ) {
-        for (pattern, method_router) in &self.routes {
+        for (pattern, method_router, _) in &self.routes {
             let full_pattern = join_route_pattern(prefix, &pattern.raw);
             entries.extend(method_router.route_entries(&full_pattern, mount_prefix));
         }
 
-        for (nested_prefix, router) in &self.nested {
+        for (nested_prefix, router, _) in &self.nested {
             let full_prefix = join_route_pattern(prefix, nested_prefix);
             router.collect_routes(&full_prefix, Some(&full_prefix), entries);
         }
@@ -836,6 +1151,11 @@ fn normalize_route_pattern(pattern: &str) -> String {
     }
 }
 
+/// Whether two mount prefixes select the same requests.
+fn same_mount_prefix(left: &str, right: &str) -> bool {
+    left.trim_matches('/') == right.trim_matches('/')
+}
+
 /// Strip a prefix from a path, returning the remainder.
 fn strip_prefix(path: &str, prefix: &str) -> Option<String> {
     let normalized_path = if path.is_empty() { "/" } else { path };
@@ -1527,6 +1847,318 @@ mod tests {
         assert!(strip_prefix("/api//users", "/api/").is_none());
     }
 
+    // ─── Typed parameters, decoding, and conflicts ──────────────────────────
+
+    mod typed_routing {
+        use super::*;
+        use crate::observability::w3c_trace_context::TraceId;
+        use crate::web::extract::PathParams;
+        use crate::web::handler::FnHandler1;
+
+        fn body(resp: &Response) -> String {
+            String::from_utf8(resp.body.to_vec()).unwrap()
+        }
+
+        #[test]
+        fn path_params_bind_in_pattern_order() {
+            fn order(PathParams((user, order)): PathParams<(u64, u32)>) -> String {
+                format!("user={user} order={order}")
+            }
+
+            let router = Router::new().route(
+                "/users/:id/orders/:order_id",
+                get(FnHandler1::<_, PathParams<(u64, u32)>>::new(order)),
+            );
+
+            let resp = router.handle(Request::new("GET", "/users/7/orders/42"));
+            assert_eq!(resp.status, StatusCode::OK);
+            assert_eq!(body(&resp), "user=7 order=42");
+
+            let resp = router.handle(Request::new("GET", "/users/seven/orders/42"));
+            assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+            assert!(body(&resp).contains("`id`"), "{}", body(&resp));
+        }
+
+        #[test]
+        fn path_params_cover_ints_strings_and_trace_ids() {
+            fn signed(PathParams(value): PathParams<i64>) -> String {
+                value.to_string()
+            }
+            fn small(PathParams(value): PathParams<u8>) -> String {
+                value.to_string()
+            }
+            fn text(PathParams(value): PathParams<String>) -> String {
+                value
+            }
+            fn trace(PathParams(id): PathParams<TraceId>) -> String {
+                id.to_hex()
+            }
+
+            let router = Router::new()
+                .route("/signed/:v", get(FnHandler1::<_, PathParams<i64>>::new(signed)))
+                .route("/small/:v", get(FnHandler1::<_, PathParams<u8>>::new(small)))
+                .route("/text/:v", get(FnHandler1::<_, PathParams<String>>::new(text)))
+                .route("/trace/:id", get(FnHandler1::<_, PathParams<TraceId>>::new(trace)));
+
+            let resp = router.handle(Request::new("GET", "/signed/-9000000000"));
+            assert_eq!(body(&resp), "-9000000000");
+            let resp = router.handle(Request::new("GET", "/small/255"));
+            assert_eq!(body(&resp), "255");
+            let resp = router.handle(Request::new("GET", "/small/256"));
+            assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+            let resp = router.handle(Request::new("GET", "/text/caf%C3%A9%20au%2Flait"));
+            assert_eq!(body(&resp), "café au/lait");
+
+            let id = "4bf92f3577b34da6a3ce929d0e0e4736";
+            let resp = router.handle(Request::new("GET", format!("/trace/{id}")));
+            assert_eq!(resp.status, StatusCode::OK);
+            assert_eq!(body(&resp), id);
+            let resp = router.handle(Request::new("GET", "/trace/not-a-trace-id"));
+            assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+            let resp = router.handle(Request::new("GET", format!("/trace/{}", "0".repeat(32))));
+            assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+        }
+
+        #[test]
+        fn path_params_arity_mismatch_is_server_error() {
+            fn pair(PathParams((a, b)): PathParams<(u32, u32)>) -> String {
+                format!("{a}{b}")
+            }
+
+            let router = Router::new().route(
+                "/one/:a",
+                get(FnHandler1::<_, PathParams<(u32, u32)>>::new(pair)),
+            );
+            let resp = router.handle(Request::new("GET", "/one/1"));
+            assert_eq!(resp.status, StatusCode::INTERNAL_SERVER_ERROR);
+        }
+
+        #[test]
+        fn named_catch_all_captures_decoded_rest() {
+            fn rest(PathParams(path): PathParams<String>) -> String {
+                path
+            }
+
+            let router = Router::new().route(
+                "/files/*path",
+                get(FnHandler1::<_, PathParams<String>>::new(rest)),
+            );
+
+            let resp = router.handle(Request::new("GET", "/files/a/b%20c/d.txt"));
+            assert_eq!(body(&resp), "a/b c/d.txt");
+            let resp = router.handle(Request::new("GET", "/files"));
+            assert_eq!(resp.status, StatusCode::OK);
+            assert_eq!(body(&resp), "");
+        }
+
+        #[test]
+        fn malformed_percent_encoding_is_bad_request() {
+            let router = Router::new()
+                .route("/users/:id", get(FnHandler::new(ok_handler)))
+                .fallback(FnHandler::new(not_found_handler));
+
+            for path in [
+                "/users/%",
+                "/users/%4",
+                "/users/%zz",
+                "/users/%C3%28",
+                "/users/%FF",
+                "/nowhere/%E2%82",
+            ] {
+                let resp = router.handle(Request::new("GET", path));
+                assert_eq!(resp.status, StatusCode::BAD_REQUEST, "{path}");
+            }
+            let resp = router.handle(Request::new("GET", "/users/%00"));
+            assert_eq!(resp.status, StatusCode::OK);
+        }
+
+        #[test]
+        fn literal_segments_match_raw_bytes() {
+            let router = Router::new()
+                .route("/admin", get(FnHandler::new(ok_handler)))
+                .fallback(FnHandler::new(not_found_handler));
+
+            let status = |path: &str| router.handle(Request::new("GET", path)).status;
+            assert_eq!(status("/admin"), StatusCode::OK);
+            assert_eq!(status("/%61dmin"), StatusCode::NOT_FOUND);
+        }
+
+        #[test]
+        fn trailing_slash_policy() {
+            let router = Router::new()
+                .route("/users", get(FnHandler::new(ok_handler)))
+                .route("/users/:id/", get(FnHandler::new(created_handler)))
+                .fallback(FnHandler::new(not_found_handler));
+
+            let status = |path: &str| router.handle(Request::new("GET", path)).status;
+            assert_eq!(status("/users"), StatusCode::OK);
+            assert_eq!(status("/users/"), StatusCode::OK);
+            assert_eq!(status("/users/1"), StatusCode::CREATED);
+            assert_eq!(status("/users/1/"), StatusCode::CREATED);
+            assert_eq!(status("/users//1"), StatusCode::NOT_FOUND);
+
+            let conflict = Router::new()
+                .route("/health", get(FnHandler::new(ok_handler)))
+                .try_route("/health/", get(FnHandler::new(ok_handler)))
+                .err()
+                .expect("trailing slash does not make a distinct route");
+            assert_eq!(conflict.method.as_deref(), Some("GET"));
+        }
+
+        #[test]
+        fn equally_specific_overlaps_conflict_with_both_sites() {
+            let cases = [
+                ("/users/:id", "/users/:name"),
+                ("/health", "/health"),
+                ("/files/*", "/files/*rest"),
+                ("/a/:x", "/:y/b"),
+                ("/:a/*", "/:b/*"),
+            ];
+            for (first, second) in cases {
+                let conflict = Router::new()
+                    .route(first, get(FnHandler::new(ok_handler)))
+                    .try_route(
+                        second,
+                        get(FnHandler::new(ok_handler)).post(FnHandler::new(ok_handler)),
+                    )
+                    .err()
+                    .unwrap_or_else(|| panic!("{first} vs {second} must conflict"));
+                assert_eq!(conflict.method.as_deref(), Some("GET"));
+                assert_eq!(conflict.existing, first);
+                assert_eq!(conflict.conflicting, second);
+                assert!(conflict.existing_site.file().ends_with("router.rs"));
+                assert!(conflict.existing_site.line() < conflict.conflicting_site.line());
+
+                let message = conflict.to_string();
+                assert!(message.contains(&conflict.existing_site.to_string()), "{message}");
+                assert!(message.contains(&conflict.conflicting_site.to_string()), "{message}");
+            }
+        }
+
+        #[test]
+        fn specificity_ordered_overlaps_do_not_conflict() {
+            let router = Router::new()
+                .try_route("/users/me", get(FnHandler::new(ok_handler)))
+                .and_then(|r| r.try_route("/users/:id", get(FnHandler::new(created_handler))))
+                .and_then(|r| r.try_route("/users/*", get(FnHandler::new(not_found_handler))))
+                .and_then(|r| r.try_route("/users/:id/:tab", get(FnHandler::new(ok_handler))))
+                .and_then(|r| r.try_route("/:any/*", get(FnHandler::new(ok_handler))))
+                .and_then(|r| r.try_route("/posts/:id", get(FnHandler::new(ok_handler))))
+                .expect("precedence separates every pair");
+            assert_eq!(router.route_count(), 6);
+        }
+
+        #[test]
+        #[should_panic(expected = "route conflict: GET /users/:name")]
+        fn route_panics_on_conflict() {
+            let _ = Router::new()
+                .route("/users/:id", get(FnHandler::new(ok_handler)))
+                .route("/users/:name", get(FnHandler::new(ok_handler)));
+        }
+
+        #[test]
+        fn disjoint_methods_share_a_pattern_and_merge_allow() {
+            fn post_handler() -> &'static str {
+                "post"
+            }
+
+            let router = Router::new()
+                .route("/items", get(FnHandler::new(ok_handler)))
+                .route("/items", post(FnHandler::new(post_handler)))
+                .route("/a/:x", get(FnHandler::new(ok_handler)))
+                .route(
+                    "/:y/b",
+                    post(FnHandler::new(post_handler)).put(FnHandler::new(ok_handler)),
+                );
+
+            let resp = router.handle(Request::new("GET", "/items"));
+            assert_eq!(body(&resp), "ok");
+            let resp = router.handle(Request::new("POST", "/items"));
+            assert_eq!(body(&resp), "post");
+            let resp = router.handle(Request::new("DELETE", "/items"));
+            assert_eq!(resp.status, StatusCode::METHOD_NOT_ALLOWED);
+            assert_eq!(resp.header_value("allow"), Some("GET, POST"));
+
+            // Both patterns match `/a/b`; each method finds its own route.
+            let resp = router.handle(Request::new("POST", "/a/b"));
+            assert_eq!(body(&resp), "post");
+            let resp = router.handle(Request::new("GET", "/a/b"));
+            assert_eq!(body(&resp), "ok");
+            let resp = router.handle(Request::new("DELETE", "/a/b"));
+            assert_eq!(resp.header_value("allow"), Some("GET, POST, PUT"));
+
+            // Only `/a/:x` matches `/a/c`, so only its methods are allowed.
+            let resp = router.handle(Request::new("DELETE", "/a/c"));
+            assert_eq!(resp.status, StatusCode::METHOD_NOT_ALLOWED);
+            assert_eq!(resp.header_value("allow"), Some("GET"));
+        }
+
+        #[test]
+        fn nesting_twice_at_one_prefix_conflicts() {
+            let conflict = Router::new()
+                .nest("/api", Router::new())
+                .try_nest("/api/", Router::new())
+                .err()
+                .expect("second router at /api is unreachable");
+            assert_eq!(conflict.method, None);
+            assert_eq!(conflict.existing, "/api");
+            assert!(conflict.to_string().contains("nested at /api/"));
+
+            let distinct = Router::new()
+                .nest("/api", Router::new())
+                .try_nest("/api/v1", Router::new());
+            assert!(distinct.is_ok());
+        }
+
+        /// Random paths over an alphabet biased toward escapes, separators,
+        /// and pattern metacharacters: the matcher must answer every one.
+        #[test]
+        fn matcher_fuzz_is_panic_free() {
+            const PIECES: &[&str] = &[
+                "/", "//", "%", "%2", "%2F", "%zz", "%C3", "%A9", "%FF", "%00", "a", "users", ":id",
+                "*", "..", "é", "\u{FF0F}", "files", "42", " ",
+            ];
+            let router = Router::new()
+                .route("/users/:id", get(FnHandler::new(ok_handler)))
+                .route("/users/me", get(FnHandler::new(ok_handler)))
+                .route("/files/*path", get(FnHandler::new(ok_handler)))
+                .route("/:a/:b", post(FnHandler::new(ok_handler)))
+                .nest("/users", Router::new().route("/*", get(FnHandler::new(ok_handler))))
+                .fallback(FnHandler::new(not_found_handler));
+
+            let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+            let mut next = move || {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            };
+            for _ in 0..4096 {
+                let len = next() % 12;
+                let path: String = (0..len)
+                    .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+                    .collect();
+                let method = if next() % 2 == 0 { "GET" } else { "POST" };
+                let resp = router.handle(Request::new(method, path.clone()));
+                assert!(
+                    matches!(
+                        resp.status,
+                        StatusCode::OK
+                            | StatusCode::NOT_FOUND
+                            | StatusCode::BAD_REQUEST
+                            | StatusCode::METHOD_NOT_ALLOWED
+                    ),
+                    "{path:?} -> {:?}",
+                    resp.status
+                );
+                for pattern in ["/users/:id", "/files/*path", "/:a/:b", "/*"] {
+                    let _ = RoutePattern::parse(pattern).matches(&path);
+                    let _ = RoutePattern::parse(&path).overlaps(&RoutePattern::parse(pattern));
+                }
+            }
+        }
+    }
+
     /// AUDIT MODULE: Route precedence verification
     ///
     /// AUDIT FINDING: SOUND - Router correctly prioritizes literal segments over
@@ -2074,6 +2706,30 @@ mod tests {
             );
         }
 
+        #[test]
+        fn nested_prefix_layers_run_inside_parent_layers() {
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let api = Router::new()
+                .route("/users/:id", get(recording_handler(Arc::clone(&log))))
+                .layer(RecordingLayer::new("api", Arc::clone(&log)));
+            let router = Router::new()
+                .route("/health", get(recording_handler(Arc::clone(&log))))
+                .nest("/api/v1", api)
+                .layer(RecordingLayer::new("app", Arc::clone(&log)));
+
+            let resp = router.handle(Request::new("GET", "/api/v1/users/7"));
+            assert_eq!(resp.status, StatusCode::OK);
+            assert_eq!(
+                std::mem::take(&mut *log.lock().expect("log lock")),
+                vec!["enter:app", "enter:api", "handler", "exit:api", "exit:app"]
+            );
+
+            // Routes outside the prefix only see the shared parent layer.
+            let resp = router.handle(Request::new("GET", "/health"));
+            assert_eq!(resp.status, StatusCode::OK);
+            assert_eq!(*log.lock().expect("log lock"), vec!["enter:app", "handler", "exit:app"]);
+        }
+
         #[test]
         fn builtin_middleware_layers_compose_on_router() {
             use crate::web::middleware::{
@@ -1124,3 +1124,83 @@ fn bytes_mut_advance_flush_discard_is_zero_alloc_vs_split_to() {
         split_to_allocs = split_to_allocs
     );
 }
+
+/// Allocation profile of small-payload protocol parsing with inline `Bytes` /
+/// `BytesMut` storage. A length-prefixed stream of small frames is parsed the
+/// way codecs do it (`split_to` header, `split_to` payload, `freeze`, clone for
+/// fan-out). Frames that fit inline parse without touching the allocator; the
+/// same workload with payloads one byte past the inline capacity pays a head
+/// `Vec` plus a shared `Arc` per frame, which is what every frame cost before
+/// inline storage.
+#[test]
+fn bytes_small_frame_parsing_is_zero_alloc_inline() {
+    use asupersync::bytes::{Bytes, BytesMut};
+
+    let _guard = ALLOC_TEST_GUARD.lock();
+    init_test("bytes_small_frame_parsing_is_zero_alloc_inline");
+
+    const FRAMES: usize = 256;
+
+    fn wire(payload_len: usize) -> BytesMut {
+        let mut buf = BytesMut::with_capacity(FRAMES * (payload_len + 2));
+        for i in 0..FRAMES {
+            buf.put_slice(&[0x01, payload_len as u8]);
+            buf.put_slice(&vec![i as u8; payload_len]);
+        }
+        buf
+    }
+
+    fn parse(mut buf: BytesMut) -> (u64, usize) {
+        let mut frames = Vec::with_capacity(FRAMES);
+        let before = AllocSnapshot::take();
+        while !buf.is_empty() {
+            let header = buf.split_to(2);
+            let payload = buf.split_to(usize::from(header[1])).freeze();
+            let fanout = payload.clone();
+            frames.push((payload, fanout));
+        }
+        let allocs = AllocSnapshot::take().allocs_since(&before);
+        (allocs, frames.len())
+    }
+
+    // A) Payloads at the BytesMut inline capacity stay inline end to end.
+    test_section!("measure-inline-frames");
+    let (inline_allocs, inline_frames) = parse(wire(BytesMut::INLINE_CAPACITY));
+
+    // B) Payloads just past the Bytes inline capacity take the heap path.
+    test_section!("measure-heap-frames");
+    let (heap_allocs, heap_frames) = parse(wire(Bytes::INLINE_CAPACITY + 1));
+
+    tracing::info!(
+        inline_allocs,
+        heap_allocs,
+        frames = FRAMES,
+        "small-frame parse A/B allocations"
+    );
+
+    assert_with_log!(
+        inline_frames == FRAMES && heap_frames == FRAMES,
+        "every frame parsed",
+        FRAMES,
+        (inline_frames, heap_frames)
+    );
+    // Small tolerance for parallel allocator-counter noise, as above.
+    assert_with_log!(
+        inline_allocs <= 2,
+        "inline small-frame parsing is ~zero-alloc",
+        "<=2",
+        inline_allocs
+    );
+    assert_with_log!(
+        heap_allocs >= 2 * FRAMES as u64,
+        "heap frames allocate a head and a shared backing each",
+        format!(">= {}", 2 * FRAMES),
+        heap_allocs
+    );
+
+    test_complete!(
+        "bytes_small_frame_parsing_is_zero_alloc_inline",
+        inline_allocs = inline_allocs,
+        heap_allocs = heap_allocs
+    );
+}
@@ -23,11 +23,13 @@ mod common;
 
 use common::init_test_logging;
 
+use asupersync::config::ResourceConfig;
 use asupersync::lab::chaos::ChaosConfig;
 use asupersync::lab::{LabConfig, LabRuntime};
 use asupersync::runtime::config::AdaptiveReadyBatchConfig;
 use asupersync::runtime::deadline_monitor::{AdaptiveDeadlineConfig, MonitorConfig};
-use asupersync::runtime::{RegionLimits, RuntimeBuilder, SpawnError};
+use asupersync::runtime::scheduler::RegionFairness;
+use asupersync::runtime::{CapacityScope, RegionLimits, RuntimeBuilder, SpawnError};
 use asupersync::types::Time;
 
 use std::sync::Arc;
@@ -229,6 +231,228 @@ fn builder_verify_006b_root_region_limits() {
     test_complete!("builder_verify_006b_root_region_limits");
 }
 
+/// BUILDER-VERIFY-006C: Runtime live-task limit
+///
+/// `try_spawn` past `max_live_tasks` fails with `AtCapacity`; `spawn` and
+/// `spawn_with_cx` block the spawning thread until a task completes and never
+/// drop their future.
+#[test]
+fn builder_verify_006c_max_live_tasks() {
+    init_test("builder_verify_006c_max_live_tasks");
+
+    let runtime = RuntimeBuilder::new()
+        .worker_threads(1)
+        .max_live_tasks(1)
+        .build()
+        .expect("build with live-task limit should succeed");
+    let handle = runtime.handle();
+
+    let release = Arc::new(AtomicBool::new(false));
+    let gate = Arc::clone(&release);
+    let blocker = handle
+        .try_spawn(async move {
+            while !gate.load(Ordering::Acquire) {
+                asupersync::runtime::yield_now().await;
+            }
+        })
+        .expect("first task fits");
+
+    let result = handle.try_spawn(async { 1_u8 });
+    assert!(
+        matches!(
+            result,
+            Err(SpawnError::AtCapacity {
+                scope: CapacityScope::Runtime,
+                limit: 1,
+                current: 1,
+            })
+        ),
+        "expected AtCapacity, got {result:?}"
+    );
+
+    let spawner_handle = handle.clone();
+    let spawner = std::thread::spawn(move || spawner_handle.spawn(async { 7_u8 }));
+    while runtime.spawn_capacity_metrics().waiting == 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(!spawner.is_finished(), "spawn blocks while at capacity");
+
+    release.store(true, Ordering::Release);
+    runtime.block_on(blocker);
+    let join = spawner.join().expect("spawner thread");
+    assert_eq!(runtime.block_on(join), 7);
+
+    let metrics = runtime.spawn_capacity_metrics();
+    assert_eq!(metrics.rejections, 1);
+    assert_eq!(metrics.waits, 1);
+    assert_eq!(metrics.waiting, 0);
+
+    // `spawn_with_cx` waits for capacity the same way.
+    release.store(false, Ordering::Release);
+    let gate = Arc::clone(&release);
+    let blocker = handle
+        .try_spawn(async move {
+            while !gate.load(Ordering::Acquire) {
+                asupersync::runtime::yield_now().await;
+            }
+        })
+        .expect("blocker fits again");
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran_flag = Arc::clone(&ran);
+    let spawner_handle = handle.clone();
+    let spawner = std::thread::spawn(move || {
+        spawner_handle.spawn_with_cx(move |_cx| async move {
+            ran_flag.store(true, Ordering::Release);
+        });
+    });
+    while runtime.spawn_capacity_metrics().waiting == 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(!spawner.is_finished(), "spawn_with_cx blocks while at capacity");
+
+    release.store(true, Ordering::Release);
+    runtime.block_on(blocker);
+    spawner.join().expect("spawner thread");
+    while !ran.load(Ordering::Acquire) {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(runtime.spawn_capacity_metrics().waits, 2);
+
+    test_complete!("builder_verify_006c_max_live_tasks");
+}
+
+/// BUILDER-VERIFY-006D: Cancelling a spawner waiting for capacity
+///
+/// `spawn_when_available` queues at the live-task limit without blocking the
+/// thread; dropping it leaves the queue and drops the future unspawned. On a
+/// worker thread, `spawn` at capacity panics instead of blocking the worker.
+#[test]
+fn builder_verify_006d_cancel_blocked_spawner() {
+    init_test("builder_verify_006d_cancel_blocked_spawner");
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let runtime = RuntimeBuilder::new()
+        .worker_threads(1)
+        .max_live_tasks(1)
+        .build()
+        .expect("build with live-task limit should succeed");
+    let handle = runtime.handle();
+
+    let release = Arc::new(AtomicBool::new(false));
+    let gate = Arc::clone(&release);
+    let blocker = handle
+        .try_spawn(async move {
+            while !gate.load(Ordering::Acquire) {
+                asupersync::runtime::yield_now().await;
+            }
+        })
+        .expect("first task fits");
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let guard = DropFlag(Arc::clone(&dropped));
+    let mut spawner = Box::pin(handle.spawn_when_available(async move {
+        let _guard = guard;
+        7_u8
+    }));
+    let mut task_cx = std::task::Context::from_waker(std::task::Waker::noop());
+    assert!(
+        spawner.as_mut().poll(&mut task_cx).is_pending(),
+        "spawner waits at capacity"
+    );
+    assert_eq!(runtime.spawn_capacity_metrics().waiting, 1);
+
+    drop(spawner);
+    assert_eq!(
+        runtime.spawn_capacity_metrics().waiting,
+        0,
+        "cancelled spawner leaves the queue"
+    );
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "cancelled spawner drops its future unspawned"
+    );
+
+    release.store(true, Ordering::Release);
+    runtime.block_on(blocker);
+
+    let worker_handle = handle.clone();
+    let on_worker = handle.spawn(async move {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            worker_handle.spawn(async {});
+        }))
+        .is_err()
+    });
+    assert!(
+        runtime.block_on(on_worker),
+        "spawn at capacity on a worker panics instead of blocking"
+    );
+    assert_eq!(runtime.spawn_capacity_metrics().waiting, 0);
+
+    test_complete!("builder_verify_006d_cancel_blocked_spawner");
+}
+
+/// BUILDER-VERIFY-006E: Region-fair scheduling from `ResourceConfig`
+///
+/// The fairness mode and default weight come from the resource config,
+/// region weights change through the handle, and the runtime reports
+/// per-region virtual runtime.
+#[test]
+fn builder_verify_006e_region_fairness() {
+    init_test("builder_verify_006e_region_fairness");
+
+    let resources = ResourceConfig {
+        region_fairness: RegionFairness::RegionFair,
+        default_region_weight: 2048,
+        ..ResourceConfig::default()
+    };
+    let runtime = RuntimeBuilder::new()
+        .worker_threads(2)
+        .region_fairness_from(&resources)
+        .build()
+        .expect("build with region fairness should succeed");
+    assert_eq!(runtime.config().region_fairness, RegionFairness::RegionFair);
+    assert_eq!(runtime.config().default_region_weight, 2048);
+    let handle = runtime.handle();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    handle.spawn_with_cx(move |cx| async move {
+        let _ = tx.send(cx.region_id());
+    });
+    let root = rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("root region id");
+    assert!(handle.set_region_weight(root, 512));
+
+    let joins: Vec<_> = (0..32_u32)
+        .map(|i| {
+            handle.spawn(async move {
+                asupersync::runtime::yield_now().await;
+                i
+            })
+        })
+        .collect();
+    let total: u32 = joins.into_iter().map(|join| runtime.block_on(join)).sum();
+    assert_eq!(total, (0..32).sum());
+
+    let accounted = runtime
+        .region_vruntimes()
+        .into_iter()
+        .find(|entry| entry.region == root)
+        .expect("weighted region stays accounted");
+    assert_eq!(accounted.weight, 512);
+    assert!(accounted.dispatched >= 64, "{accounted:?}");
+    assert!(accounted.vruntime > 0);
+
+    test_complete!("builder_verify_006e_region_fairness");
+}
+
 // =============================================================================
 // RuntimeBuilder Presets (007-010)
 // =============================================================================
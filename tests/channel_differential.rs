@@ -0,0 +1,37 @@
+//! Lab-vs-native differential sweeps over the channel suite.
+//!
+//! - `bounded_sweep_matches_spec`: a fixed set of generated programs under
+//!   the default (bounded) configuration; runs on every CI build.
+//! - `long_running_sweep_matches_spec`: many more programs under the nightly
+//!   configuration. Ignored by default; run it with
+//!   `cargo test --test channel_differential -- --ignored`. Set
+//!   `ASUPERSYNC_CHANNEL_DIFF_PROGRAMS` to change the program count.
+#![allow(missing_docs)]
+
+use asupersync::lab::channel_differential::{
+    ChannelProgram, ChannelSuite, DifferentialConfig, run_differential,
+};
+
+fn sweep(programs: u64, max_ops: usize, config: &DifferentialConfig) {
+    for seed in 0..programs {
+        let program = ChannelProgram::generate(seed, max_ops);
+        if let Err(failure) = run_differential(&ChannelSuite, &program, config, seed) {
+            panic!("program seed {seed} diverged:\n{failure}");
+        }
+    }
+}
+
+#[test]
+fn bounded_sweep_matches_spec() {
+    sweep(16, 8, &DifferentialConfig::default());
+}
+
+#[test]
+#[ignore = "long-running differential sweep; run nightly"]
+fn long_running_sweep_matches_spec() {
+    let programs = std::env::var("ASUPERSYNC_CHANNEL_DIFF_PROGRAMS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(500);
+    sweep(programs, 24, &DifferentialConfig::nightly());
+}
@@ -265,6 +265,10 @@ fn action_name(action: &FaultAction) -> &'static str {
         FaultAction::HostRestart => "host_restart",
         FaultAction::ClockSkew => "clock_skew",
         FaultAction::ClockReset => "clock_reset",
+        FaultAction::LinkDegrade => "link_degrade",
+        FaultAction::LinkRestore => "link_restore",
+        FaultAction::PartitionSubtree => "partition_subtree",
+        FaultAction::HealSubtree => "heal_subtree",
     }
 }
 
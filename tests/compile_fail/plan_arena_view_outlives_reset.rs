@@ -0,0 +1,14 @@
+//! A node view borrowed from an arena plan cannot outlive the next reset:
+//! `PlanArena::begin` needs the arena mutably while the view still points
+//! into it.
+
+use asupersync::plan::{ArenaNodeRef, PlanArena};
+
+fn main() {
+    let mut arena = PlanArena::new();
+    let mut plan = arena.begin();
+    let leaf = plan.leaf("fetch");
+    let view = plan.node(leaf);
+    let _next = arena.begin();
+    assert!(matches!(view, Some(ArenaNodeRef::Leaf { .. })));
+}
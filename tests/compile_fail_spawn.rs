@@ -1,8 +1,9 @@
 //! Compile-fail contracts for typestate and capability-gated APIs.
 //!
-//! The v2 spawn surface requires `HasSpawn` (br-asupersync-69ftra), and
+//! The v2 spawn surface requires `HasSpawn` (br-asupersync-69ftra),
 //! database transactions consume their handle on commit/rollback
-//! (br-asupersync-server-stack-hardening-eeexl1.5).
+//! (br-asupersync-server-stack-hardening-eeexl1.5), and plan arena views
+//! cannot outlive the next arena reset.
 
 #[test]
 #[ignore = "cold trybuild compile-fail lane; run explicitly with `cargo test --test compile_fail_spawn -- --ignored`"]
@@ -10,6 +11,7 @@ fn compile_fail() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/compile_fail/spawn_without_capability.rs");
     t.compile_fail("tests/compile_fail/handler_arity_9.rs");
+    t.compile_fail("tests/compile_fail/plan_arena_view_outlives_reset.rs");
 
     if cfg!(feature = "postgres") {
         t.compile_fail("tests/compile_fail/database_transaction_consumes_self.rs");
@@ -61,6 +61,7 @@ struct FixtureExpectation {
     expected_families: Option<Vec<String>>,
     expected_risk_classes: Option<Vec<String>>,
     expected_asup_codes: Option<Vec<String>>,
+    expected_finding_ids: Option<Vec<String>>,
     repro_command: String,
 }
 
@@ -532,6 +533,18 @@ fn execute_fixture(fixture: &AnalyzerFixture) -> FixtureExecutionLog {
                 "finding_count",
                 &mut diagnostics,
             );
+            if let Some(expected_finding_ids) = &fixture.expectation.expected_finding_ids {
+                let observed_finding_ids = analysis
+                    .findings
+                    .iter()
+                    .map(|finding| finding.finding_id.as_str())
+                    .collect::<BTreeSet<_>>();
+                for finding_id in expected_finding_ids {
+                    if !observed_finding_ids.contains(finding_id.as_str()) {
+                        diagnostics.push(format!("missing invariant finding `{finding_id}`"));
+                    }
+                }
+            }
         }
         AnalyzerFamily::LockContention => {
             let workspace_root = fixture
@@ -882,6 +895,18 @@ fn ingestion_source_adapter_matrix() {
     );
 }
 
+#[test]
+fn invariant_lab_ambient_randomness() {
+    let pack = load_fixture_pack();
+    let log = run_fixture_by_id(&pack, "invariant_lab_ambient_randomness");
+    assert_eq!(
+        log.status,
+        "pass",
+        "fixture failed: {}",
+        serde_json::to_string_pretty(&log).expect("serialize fixture log")
+    );
+}
+
 #[test]
 fn evidence_analysis_d2_matrix() {
     let pack = load_fixture_pack();
@@ -0,0 +1,243 @@
+#![allow(missing_docs)]
+#![cfg(feature = "cli")]
+//! Capability audit: declared manifests diffed against static and runtime
+//! capability usage, and runtime enforcement of the declared set.
+
+use asupersync::Cx;
+use asupersync::cli::doctor::{
+    CapabilityAuditReport, audit_workspace_capabilities, parse_capability_manifest,
+    parse_capability_usage_log, scan_workspace,
+};
+use asupersync::cx::{Capability, CapabilityAudit, CapabilityAuditMode, CapabilitySet};
+use asupersync::net::TcpListener;
+use asupersync::runtime::{RuntimeBuilder, SpawnError};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+const FIXTURE_ROOT: &str = "tests/fixtures/doctor_capability_audit";
+
+fn member_audit(member: &str, mode: CapabilityAuditMode) -> Arc<CapabilityAudit> {
+    let manifest_path = Path::new(FIXTURE_ROOT)
+        .join(member)
+        .join("capabilities.toml");
+    let text = fs::read_to_string(manifest_path).expect("read fixture manifest");
+    let manifest = parse_capability_manifest(&text).expect("fixture manifest is valid");
+    Arc::new(CapabilityAudit::new(member, manifest.capability_set(), mode))
+}
+
+fn audit(usage_logs: &[String]) -> CapabilityAuditReport {
+    let scan = scan_workspace(Path::new(FIXTURE_ROOT)).expect("scan fixture workspace");
+    let logs = usage_logs
+        .iter()
+        .map(|log| parse_capability_usage_log(log).expect("usage log parses"))
+        .collect::<Vec<_>>();
+    audit_workspace_capabilities(&scan, &logs)
+}
+
+/// Runs the gateway's network entry point under `audit` and returns the
+/// bind result.
+fn bind_under(audit: &Arc<CapabilityAudit>) -> io::Result<()> {
+    let runtime = RuntimeBuilder::current_thread()
+        .capability_audit(Arc::clone(audit))
+        .build()
+        .expect("runtime build");
+    runtime.block_on(runtime.handle().spawn(async {
+        TcpListener::bind("127.0.0.1:0").await.map(drop)
+    }))
+}
+
+#[test]
+fn undeclared_net_is_caught_statically() {
+    let report = audit(&[]);
+    assert_eq!(report.status, "fail");
+
+    let undeclared = report
+        .findings
+        .iter()
+        .find(|finding| finding.finding_id == "capability-undeclared_use:gateway:net")
+        .expect("gateway net use is undeclared");
+    assert_eq!(undeclared.severity, "failure");
+    assert_eq!(undeclared.sources, ["static"]);
+    assert!(
+        undeclared
+            .evidence
+            .iter()
+            .any(|line| line.contains("gateway/src/lib.rs:11")),
+        "{:?}",
+        undeclared.evidence
+    );
+
+    let gateway = &report.members[0];
+    assert_eq!(gateway.name, "gateway");
+    assert_eq!(gateway.declared, ["fs"]);
+    assert_eq!(gateway.static_usage, ["fs", "net"]);
+    assert!(gateway.runtime_usage.is_empty());
+}
+
+#[test]
+fn unused_declaration_is_a_warning() {
+    let report = audit(&[]);
+    let unused = report
+        .findings
+        .iter()
+        .filter(|finding| finding.kind == "unused_declaration")
+        .map(|finding| finding.finding_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(unused, ["capability-unused_declaration:worker:process"]);
+    assert_eq!(report.failure_count, 1, "{:#?}", report.findings);
+    assert_eq!(report.warning_count, 1, "{:#?}", report.findings);
+
+    // Withholding net from a plugin context is not a use of net.
+    let worker = &report.members[1];
+    assert_eq!(worker.static_usage, ["blocking"]);
+}
+
+#[test]
+fn undeclared_net_is_caught_at_runtime() {
+    let audit_log = member_audit("gateway", CapabilityAuditMode::Observe);
+    let _ = bind_under(&audit_log);
+    let uses = audit_log.uses();
+    assert_eq!(uses.len(), 1, "{uses:?}");
+    assert_eq!(uses[0].capability, Capability::Net);
+    assert!(!uses[0].declared);
+
+    let report = audit(&[audit_log.usage_log_json()]);
+    let undeclared = report
+        .findings
+        .iter()
+        .find(|finding| finding.finding_id == "capability-undeclared_use:gateway:net")
+        .expect("gateway net use is undeclared");
+    assert_eq!(undeclared.sources, ["static", "runtime"]);
+    assert_eq!(report.members[0].runtime_usage, ["net"]);
+    assert_eq!(report.usage_log_count, 1);
+}
+
+#[test]
+fn enforce_mode_denies_undeclared_usage() {
+    let gateway = member_audit("gateway", CapabilityAuditMode::Enforce);
+    let err = bind_under(&gateway).expect_err("undeclared net is denied");
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(
+        err.to_string().contains("capability-manifest:gateway"),
+        "{err}"
+    );
+
+    // The worker declares `blocking` but not `net`: blocking work is admitted
+    // while the same runtime still refuses sockets.
+    let worker = member_audit("worker", CapabilityAuditMode::Enforce);
+    let runtime = RuntimeBuilder::current_thread()
+        .capability_audit(Arc::clone(&worker))
+        .build()
+        .expect("runtime build");
+    let (blocking, net) = runtime.block_on(runtime.handle().spawn(async {
+        let cx = Cx::current().expect("task context");
+        let blocking = cx.spawn_blocking(|_| 7).map(drop);
+        let net = TcpListener::bind("127.0.0.1:0").await.map(drop);
+        (blocking, net)
+    }));
+    assert!(
+        !matches!(blocking, Err(SpawnError::CapabilityDenied(_))),
+        "{blocking:?}"
+    );
+    assert_eq!(
+        net.expect_err("net is undeclared").kind(),
+        io::ErrorKind::PermissionDenied
+    );
+    assert_eq!(
+        worker.undeclared(),
+        CapabilitySet::none().with(Capability::Net)
+    );
+}
+
+#[test]
+fn manifest_parse_validation() {
+    let manifest = parse_capability_manifest("version = 1\nallow = [\"net\", \"remote\"]\n")
+        .expect("valid manifest");
+    let expected = [Capability::Net, Capability::RemoteSpawn];
+    assert_eq!(
+        manifest.capability_set(),
+        expected.into_iter().collect::<CapabilitySet>()
+    );
+
+    for (text, expected) in [
+        (
+            "version = 2\nallow = []\n",
+            "unsupported capability manifest version 2",
+        ),
+        (
+            "version = 1\nallow = [\"sockets\"]\n",
+            "unknown capability class `sockets`",
+        ),
+        (
+            "version = 1\nallow = [\"fs\", \"fs\"]\n",
+            "`fs` is declared twice",
+        ),
+        (
+            "version = 1\ndeny = [\"net\"]\n",
+            "invalid capability manifest",
+        ),
+        ("allow = [\"net\"]\n", "invalid capability manifest"),
+    ] {
+        let err = parse_capability_manifest(text).expect_err(text);
+        assert!(err.contains(expected), "{text:?}: {err}");
+    }
+}
+
+#[test]
+fn report_json_schema_is_stable() {
+    fn key_paths(prefix: &str, value: &serde_json::Value, paths: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    let path = format!("{prefix}/{key}");
+                    key_paths(&path, child, paths);
+                    paths.push(path);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                if let Some(first) = items.first() {
+                    key_paths(&format!("{prefix}[]"), first, paths);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let report = audit(&[]);
+    let value = serde_json::to_value(&report).expect("report serializes");
+    let mut paths = Vec::new();
+    key_paths("", &value, &mut paths);
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "/failure_count",
+            "/findings",
+            "/findings[]/capability",
+            "/findings[]/evidence",
+            "/findings[]/finding_id",
+            "/findings[]/kind",
+            "/findings[]/member",
+            "/findings[]/severity",
+            "/findings[]/sources",
+            "/members",
+            "/members[]/declared",
+            "/members[]/manifest_path",
+            "/members[]/manifest_status",
+            "/members[]/name",
+            "/members[]/runtime_usage",
+            "/members[]/static_usage",
+            "/reproduction_commands",
+            "/root",
+            "/scanner_version",
+            "/schema_version",
+            "/status",
+            "/usage_log_count",
+            "/warning_count",
+            "/warnings",
+        ]
+    );
+    assert_eq!(value["schema_version"], "doctor-capability-audit-v1");
+}
@@ -0,0 +1,13 @@
+use asupersync::lab::{LabConfig, LabRuntime};
+use rand::Rng;
+
+pub fn shard_for(key: &str, shards: usize) -> usize {
+    // Escapes the lab seed: a replay picks different shards.
+    let salt: usize = rand::thread_rng().gen_range(0..shards);
+    (key.len() + salt) % shards
+}
+
+pub fn run(seed: u64) {
+    let mut runtime = LabRuntime::new(LabConfig::new(seed));
+    runtime.run_until_quiescent();
+}
@@ -0,0 +1,14 @@
+use asupersync::Cx;
+use asupersync::fs::File;
+use asupersync::net::TcpStream;
+
+pub async fn load_routes(path: &str) -> std::io::Result<File> {
+    File::open(path).await
+}
+
+pub async fn forward(cx: &Cx, upstream: &str) -> std::io::Result<()> {
+    // Undeclared: the manifest only allows `fs`.
+    let _stream = TcpStream::connect(upstream).await?;
+    cx.trace("forwarded");
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use asupersync::Cx;
+use asupersync::cx::{Capability, CapabilitySet};
+
+pub fn checksum(cx: &Cx, data: Vec<u8>) {
+    let _ = cx.spawn_blocking(move |_| data.iter().map(|&b| u64::from(b)).sum::<u64>());
+}
+
+pub fn plugin_cx(cx: &Cx) -> Cx {
+    // Withholding net is not a use of it.
+    cx.restrict_capabilities(CapabilitySet::all().without(Capability::Net), "plugin")
+        .expect("narrowing succeeds")
+}
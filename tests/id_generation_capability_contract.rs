@@ -0,0 +1,163 @@
+//! Conformance guard: FrankenSuite ids are minted through `Cx`.
+//!
+//! Runtime code must obtain fresh `TraceId`/`DecisionId` values from
+//! `Cx::next_trace_id` / `Cx::next_decision_id`, which take their timestamp
+//! from the timer driver (virtual in the lab) and their random bits from the
+//! context's entropy capability. Building ids directly with `from_parts` /
+//! `from_raw` next to ambient time or OS randomness breaks lab replay.
+//!
+//! This test reads the crate sources as text and fails if a non-test module
+//! constructs ids directly without being on the reviewed allowlist below.
+//! Every allowlisted site derives its ids purely from content or from the
+//! timer driver, never from ambient authority.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Direct constructors that bypass the `Cx` capability path.
+const DIRECT_CONSTRUCTORS: &[&str] = &[
+    "TraceId::from_parts(",
+    "DecisionId::from_parts(",
+    "TraceId::from_raw(",
+    "DecisionId::from_raw(",
+];
+
+/// Reviewed non-test sites allowed to construct ids directly (path relative
+/// to the crate root, reason).
+const ALLOWED_DIRECT_CONSTRUCTION: &[(&str, &str)] = &[
+    (
+        "src/cx/cx.rs",
+        "the capability-backed implementation of next_trace_id/next_decision_id",
+    ),
+    (
+        "src/atp/proof/bundle.rs",
+        "proof bundle ids are content hashes of the bundle",
+    ),
+    (
+        "src/bin/asupersync.rs",
+        "audit report ids are stable hashes of report and finding ids",
+    ),
+    (
+        "src/messaging/consumer.rs",
+        "decision ids are fingerprints of the consumer decision inputs",
+    ),
+    (
+        "src/messaging/control.rs",
+        "advisories without a decision carry the nil DecisionId",
+    ),
+    (
+        "src/messaging/fabric.rs",
+        "decision ids are fingerprints of subject and seed",
+    ),
+    (
+        "src/raptorq/decision_contract.rs",
+        "decision ids are fingerprints of the decoding inputs",
+    ),
+    (
+        "src/runtime/scheduler/three_lane.rs",
+        "timestamp comes from the timer driver and bits from worker id and sequence",
+    ),
+];
+
+fn crate_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn collect_rs_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let entries = std::fs::read_dir(dir)
+        .unwrap_or_else(|error| panic!("cannot read {}: {error}", dir.display()));
+    for entry in entries {
+        let path = entry.expect("directory entry").path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name == "tests") {
+                continue;
+            }
+            collect_rs_files(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+fn is_test_file(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem == "tests" || stem.ends_with("_test") || stem.ends_with("_tests"))
+}
+
+/// Returns the source up to the first `#[cfg(...test...)]`-gated `mod` block.
+fn non_test_source(source: &str) -> &str {
+    let mut offset = 0;
+    let mut lines = source.lines().peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("#[cfg(") && trimmed.contains("test") {
+            let next = lines.peek().map_or("", |next| next.trim_start());
+            if next.starts_with("mod ") || next.starts_with("pub(crate) mod ") {
+                return &source[..offset];
+            }
+        }
+        offset += line.len() + 1;
+    }
+    source
+}
+
+#[test]
+fn runtime_ids_are_minted_through_cx() {
+    let root = crate_root();
+    let mut files = Vec::new();
+    collect_rs_files(&root.join("src"), &mut files);
+    files.sort();
+
+    let allowed: BTreeSet<&str> = ALLOWED_DIRECT_CONSTRUCTION
+        .iter()
+        .map(|(path, _)| *path)
+        .collect();
+    let mut constructing = BTreeSet::new();
+    for path in files.iter().filter(|path| !is_test_file(path)) {
+        let source = std::fs::read_to_string(path)
+            .unwrap_or_else(|error| panic!("cannot read {}: {error}", path.display()));
+        let code = non_test_source(&source);
+        if DIRECT_CONSTRUCTORS.iter().any(|pat| code.contains(pat)) {
+            let relative = path
+                .strip_prefix(&root)
+                .expect("source under crate root")
+                .to_string_lossy()
+                .replace('\\', "/");
+            constructing.insert(relative);
+        }
+    }
+
+    let unexpected: Vec<_> = constructing
+        .iter()
+        .filter(|path| !allowed.contains(path.as_str()))
+        .collect();
+    assert!(
+        unexpected.is_empty(),
+        "direct TraceId/DecisionId construction outside the reviewed allowlist: \
+         {unexpected:?}. Mint runtime ids with cx.next_trace_id() / \
+         cx.next_decision_id() so lab runs replay them; if the ids are purely \
+         content-derived, add the file to ALLOWED_DIRECT_CONSTRUCTION with a reason."
+    );
+
+    let stale: Vec<_> = allowed
+        .iter()
+        .filter(|path| !constructing.contains(**path))
+        .collect();
+    assert!(
+        stale.is_empty(),
+        "allowlisted files no longer construct ids directly; remove them: {stale:?}"
+    );
+}
+
+#[test]
+fn cx_exposes_id_minting_api() {
+    let cx_source =
+        std::fs::read_to_string(crate_root().join("src/cx/cx.rs")).expect("read src/cx/cx.rs");
+    for api in ["pub fn next_trace_id(", "pub fn next_decision_id("] {
+        assert!(
+            cx_source.contains(api),
+            "src/cx/cx.rs must expose `{api}` as the id-minting capability"
+        );
+    }
+}
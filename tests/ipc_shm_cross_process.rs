@@ -0,0 +1,246 @@
+#![allow(missing_docs)]
+#![cfg(all(unix, feature = "test-internals"))]
+//! Cross-process integration tests for the shared-memory IPC channel
+//! (`asupersync::channel::ipc`):
+//!   - `echo_round_trip_across_processes_preserves_integrity`: frames of
+//!     varying size go to a child process over one ring and come back over a
+//!     second ring the child created; every byte is checked, the echo is read
+//!     zero-copy, and closing the parent's sender shuts the child down.
+//!   - `killed_producer_is_reported_as_disconnected`: a receiver waiting on a
+//!     producer that is SIGKILLed observes `RecvError::Disconnected` promptly.
+//!   - `stalled_consumer_applies_backpressure_then_kill_disconnects`: a child
+//!     that never receives fills the ring, `try_reserve` reports `Full`, a
+//!     waiting `reserve` stays pending, and killing the child resolves it to
+//!     `SendError::Disconnected`.
+//!
+//! The child is this test binary re-executed with `--exact child_process_entry`
+//! and a role in the environment; it meets the parent on a Unix socket in a
+//! temporary directory.
+
+use asupersync::Cx;
+use asupersync::channel::ipc::{self, IpcConfig, Receiver, RecvError, SendError, Sender};
+use asupersync::net::unix::UnixStream;
+use futures_lite::future::block_on;
+use std::future::Future;
+use std::os::unix::net::{UnixListener, UnixStream as StdUnixStream};
+use std::path::Path;
+use std::pin::Pin;
+use std::process::{Child, Command};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const ROLE_ENV: &str = "ASUPERSYNC_IPC_CHILD_ROLE";
+const SOCKET_ENV: &str = "ASUPERSYNC_IPC_CHILD_SOCKET";
+const ECHO_FRAMES: usize = 200;
+const KILL_DETECTION_BUDGET: Duration = Duration::from_secs(2);
+
+/// Kills and reaps the child if a test fails before doing so itself.
+struct ChildGuard(Child);
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn spawn_child(role: &str, socket: &Path) -> ChildGuard {
+    let child = Command::new(std::env::current_exe().expect("test binary path"))
+        .args([
+            "child_process_entry",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(ROLE_ENV, role)
+        .env(SOCKET_ENV, socket)
+        .spawn()
+        .expect("spawn child process");
+    ChildGuard(child)
+}
+
+fn accept(listener: &UnixListener) -> UnixStream {
+    let (stream, _) = listener.accept().expect("accept child connection");
+    UnixStream::from_std(stream).expect("nonblocking stream")
+}
+
+fn connect(socket: &Path) -> UnixStream {
+    let stream = StdUnixStream::connect(socket).expect("connect to parent");
+    UnixStream::from_std(stream).expect("nonblocking stream")
+}
+
+fn open_sender(stream: UnixStream, config: IpcConfig) -> Sender<Vec<u8>> {
+    let handshake = ipc::sender(stream, config);
+    block_on(handshake).expect("ipc sender handshake")
+}
+
+fn open_receiver(stream: UnixStream) -> Receiver<Vec<u8>> {
+    let handshake = ipc::receiver(stream);
+    block_on(handshake).expect("ipc receiver handshake")
+}
+
+/// Deterministic payload for frame `index`: sizes sweep from 1 byte to 32 KiB.
+fn frame_payload(index: usize) -> Vec<u8> {
+    let len = 1 + (index * 7919) % (32 * 1024);
+    (0..len)
+        .map(|offset| ((index * 31 + offset * 17) % 251) as u8)
+        .collect()
+}
+
+/// Polls `future` until it completes or `deadline` passes.
+fn poll_until<F: Future + Unpin>(future: &mut F, deadline: Instant) -> Option<F::Output> {
+    let mut task = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = Pin::new(&mut *future).poll(&mut task) {
+            return Some(output);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Entry point for the re-executed child; a no-op in the parent's test run.
+#[test]
+fn child_process_entry() {
+    let Ok(role) = std::env::var(ROLE_ENV) else {
+        return;
+    };
+    let socket = std::env::var(SOCKET_ENV).expect("child socket path");
+    let socket = Path::new(&socket);
+    let cx = Cx::for_testing();
+
+    match role.as_str() {
+        "echo" => {
+            let mut rx = open_receiver(connect(socket));
+            let tx = open_sender(connect(socket), IpcConfig::default());
+            loop {
+                match block_on(rx.recv(&cx)) {
+                    Ok(frame) => block_on(tx.send(&cx, frame)).expect("echo frame"),
+                    Err(RecvError::Disconnected) => break,
+                    Err(err) => panic!("child receive failed: {err}"),
+                }
+            }
+        }
+        "stall-producer" => {
+            let tx = open_sender(connect(socket), IpcConfig::default());
+            block_on(tx.send(&cx, b"before-stall".to_vec())).expect("send");
+            loop {
+                std::thread::sleep(Duration::from_secs(60));
+            }
+        }
+        "stall-consumer" => {
+            let _rx = open_receiver(connect(socket));
+            loop {
+                std::thread::sleep(Duration::from_secs(60));
+            }
+        }
+        other => panic!("unknown child role {other}"),
+    }
+}
+
+#[test]
+fn echo_round_trip_across_processes_preserves_integrity() {
+    let dir = TempDir::new().expect("tempdir");
+    let socket = dir.path().join("echo.sock");
+    let listener = UnixListener::bind(&socket).expect("bind");
+    let mut child = spawn_child("echo", &socket);
+    let cx = Cx::for_testing();
+
+    let config = IpcConfig::default().with_slots(8).with_slot_size(32 * 1024);
+    let tx = open_sender(accept(&listener), config);
+    let mut rx = open_receiver(accept(&listener));
+
+    for index in 0..ECHO_FRAMES {
+        let payload = frame_payload(index);
+        block_on(tx.send(&cx, payload.clone())).expect("send");
+        let echoed = block_on(rx.recv_ref(&cx)).expect("echo");
+        assert_eq!(
+            echoed.bytes(),
+            payload.as_slice(),
+            "frame {index} must round-trip byte for byte"
+        );
+        echoed.release();
+    }
+    assert_eq!(rx.unreleased_refs(), 0, "every echoed frame was released");
+
+    drop(tx);
+    let closed = block_on(rx.recv(&cx));
+    assert_eq!(
+        closed,
+        Err(RecvError::Disconnected),
+        "child exits once its receiver disconnects"
+    );
+    let status = child.0.wait().expect("child exit status");
+    assert!(status.success(), "echo child exited cleanly: {status}");
+}
+
+#[test]
+fn killed_producer_is_reported_as_disconnected() {
+    let dir = TempDir::new().expect("tempdir");
+    let socket = dir.path().join("kill-producer.sock");
+    let listener = UnixListener::bind(&socket).expect("bind");
+    let mut child = spawn_child("stall-producer", &socket);
+    let cx = Cx::for_testing();
+
+    let mut rx = open_receiver(accept(&listener));
+    let first = block_on(rx.recv(&cx)).expect("frame before stall");
+    assert_eq!(first, b"before-stall");
+
+    let mut waiting = rx.recv(&cx);
+    let deadline = Instant::now() + Duration::from_millis(50);
+    assert!(
+        poll_until(&mut waiting, deadline).is_none(),
+        "receiver waits while the producer is alive"
+    );
+
+    let killed_at = Instant::now();
+    child.0.kill().expect("kill producer");
+    let outcome = poll_until(&mut waiting, killed_at + KILL_DETECTION_BUDGET);
+    let latency = killed_at.elapsed();
+    assert_eq!(
+        outcome,
+        Some(Err(RecvError::Disconnected)),
+        "producer death surfaces as a typed disconnection"
+    );
+    eprintln!("producer kill detected in {latency:?}");
+}
+
+#[test]
+fn stalled_consumer_applies_backpressure_then_kill_disconnects() {
+    let dir = TempDir::new().expect("tempdir");
+    let socket = dir.path().join("stall-consumer.sock");
+    let listener = UnixListener::bind(&socket).expect("bind");
+    let mut child = spawn_child("stall-consumer", &socket);
+    let cx = Cx::for_testing();
+
+    let config = IpcConfig::default().with_slots(4).with_slot_size(64);
+    let tx = open_sender(accept(&listener), config);
+    for index in 0..4_u8 {
+        block_on(tx.send(&cx, vec![index; 16])).expect("fill ring");
+    }
+    assert_eq!(tx.in_flight(), 4, "every slot holds an unreleased frame");
+    assert!(
+        matches!(tx.try_reserve(), Err(SendError::Full(()))),
+        "a full ring refuses new reservations"
+    );
+
+    let mut waiting = tx.reserve(&cx);
+    let deadline = Instant::now() + Duration::from_millis(50);
+    assert!(
+        poll_until(&mut waiting, deadline).is_none(),
+        "reserve waits while the consumer stalls"
+    );
+
+    let killed_at = Instant::now();
+    child.0.kill().expect("kill consumer");
+    let outcome = poll_until(&mut waiting, killed_at + KILL_DETECTION_BUDGET);
+    let latency = killed_at.elapsed();
+    assert!(
+        matches!(outcome, Some(Err(SendError::Disconnected(())))),
+        "consumer death surfaces as a typed disconnection, got {outcome:?}"
+    );
+    eprintln!("consumer kill detected in {latency:?}");
+}
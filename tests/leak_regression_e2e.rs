@@ -1443,6 +1443,7 @@ fn admission_limits_reject_excess_obligations() {
     let limits = RegionLimits {
         max_children: None,
         max_tasks: None,
+        max_live_tasks: None,
         max_obligations: Some(3),
         max_heap_bytes: None,
         curve_budget: None,
@@ -1517,6 +1518,7 @@ fn admission_limits_task_cap() {
     let limits = RegionLimits {
         max_children: None,
         max_tasks: Some(2),
+        max_live_tasks: None,
         max_obligations: None,
         max_heap_bytes: None,
         curve_budget: None,
@@ -1569,6 +1571,7 @@ fn admission_limits_under_stress() {
     let limits = RegionLimits {
         max_children: None,
         max_tasks: Some(4),
+        max_live_tasks: None,
         max_obligations: Some(8),
         max_heap_bytes: None,
         curve_budget: None,
@@ -1858,6 +1861,7 @@ fn child_region_admission_tracked() {
     let limits = RegionLimits {
         max_children: Some(2),
         max_tasks: None,
+        max_live_tasks: None,
         max_obligations: None,
         max_heap_bytes: None,
         curve_budget: None,
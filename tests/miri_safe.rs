@@ -0,0 +1,169 @@
+#![allow(clippy::all)]
+//! Miri-clean tests for the core synchronization primitives.
+//!
+//! Everything here avoids the filesystem, the network, wall-clock reads, and
+//! the runtime's I/O reactor, so the suite runs under Miri's default
+//! isolation. It covers the same primitives as `sync_loom.rs` (mpsc, oneshot,
+//! `Semaphore`, `TaskWakeState`) plus the deterministic utilities they build
+//! on, using manual polling and plain `std::thread`s.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo miri test -p asupersync --features miri-safe --test miri_safe
+//! ```
+//!
+//! The full unit-test suite is not Miri-clean; new tests that belong here
+//! must not touch `std::fs`, sockets, `SystemTime`, or sleeps.
+
+#![cfg(feature = "miri-safe")]
+
+use asupersync::channel::{mpsc, oneshot};
+use asupersync::cx::Cx;
+use asupersync::record::task::TaskWakeState;
+use asupersync::sync::Semaphore;
+use asupersync::util::{Arena, DetHashMap, DetRng};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Polls `future` with a no-op waker until it completes, yielding the OS
+/// thread between polls so a peer thread can make progress.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut task_cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut task_cx) {
+            return output;
+        }
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn mpsc_round_trip_preserves_order() {
+    let cx = Cx::for_testing();
+    let (tx, mut rx) = mpsc::channel::<u32>(4);
+    for value in 0..4 {
+        tx.try_send(value).expect("capacity available");
+    }
+    assert!(tx.try_send(99).is_err(), "channel is bounded");
+    drop(tx);
+
+    let received: Vec<u32> = std::iter::from_fn(|| block_on(rx.recv(&cx)).ok()).collect();
+    assert_eq!(received, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn mpsc_cross_thread_send_and_close() {
+    let (tx, mut rx) = mpsc::channel::<u32>(1);
+    let sender = thread::spawn(move || {
+        let cx = Cx::for_testing();
+        for value in 0..3 {
+            block_on(tx.send(&cx, value)).expect("receiver alive");
+        }
+    });
+
+    let cx = Cx::for_testing();
+    let mut received = Vec::new();
+    while let Ok(value) = block_on(rx.recv(&cx)) {
+        received.push(value);
+    }
+    sender.join().unwrap();
+    assert_eq!(received, vec![0, 1, 2]);
+}
+
+#[test]
+fn oneshot_send_and_drop_paths() {
+    let cx = Cx::for_testing();
+
+    let (tx, mut rx) = oneshot::channel::<String>();
+    let sender = thread::spawn(move || tx.send_blocking("done".to_owned()));
+    assert_eq!(block_on(rx.recv(&cx)).as_deref(), Ok("done"));
+    sender.join().unwrap().expect("receiver alive");
+
+    let (tx, mut rx) = oneshot::channel::<String>();
+    drop(tx);
+    assert_eq!(block_on(rx.recv(&cx)), Err(oneshot::RecvError::Closed));
+}
+
+/// Payload that counts its drops so leaks and double drops show up.
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn oneshot_payload_dropped_exactly_once_when_receiver_gone() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = oneshot::channel::<Tracked>();
+    drop(rx);
+    let result = tx.send_blocking(Tracked(Arc::clone(&drops)));
+    assert!(result.is_err());
+    drop(result);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn semaphore_permits_balance_across_threads() {
+    let semaphore = Arc::new(Semaphore::new(2));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let semaphore = Arc::clone(&semaphore);
+            thread::spawn(move || {
+                let cx = Cx::for_testing();
+                let permit = block_on(semaphore.acquire(&cx, 1)).expect("not closed");
+                assert!(semaphore.available_permits() <= 1);
+                drop(permit);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(semaphore.available_permits(), 2);
+}
+
+#[test]
+fn task_wake_state_transitions() {
+    let state = TaskWakeState::new();
+    assert!(state.notify(), "idle task schedules on first wake");
+    assert!(!state.notify(), "second wake is deduplicated");
+
+    state.begin_poll();
+    assert!(!state.finish_poll(), "quiet poll returns to idle");
+
+    state.begin_poll();
+    assert!(!state.notify(), "wake during poll does not reschedule");
+    assert!(state.finish_poll(), "wake during poll requests a repoll");
+    state.clear();
+    assert!(!state.is_notified());
+}
+
+#[test]
+fn deterministic_utilities() {
+    let mut left = DetRng::new(42);
+    let mut right = DetRng::new(42);
+    for _ in 0..16 {
+        assert_eq!(left.next_u64(), right.next_u64());
+    }
+
+    let mut map = DetHashMap::default();
+    for key in 0..32u32 {
+        map.insert(key, key * 2);
+    }
+    assert_eq!(map.get(&7), Some(&14));
+
+    let mut arena = Arena::new();
+    let first = arena.insert("a");
+    let second = arena.insert("b");
+    assert_eq!(arena.remove(first), Some("a"));
+    assert_eq!(arena.get(second), Some(&"b"));
+    assert_eq!(arena.get(first), None);
+}
@@ -0,0 +1,76 @@
+//! `panic = "abort"` compatibility: crash report contents and unwind parity.
+//!
+//! The abort test rebuilds the `panic_abort_crash_report` example with
+//! `CARGO_PROFILE_DEV_PANIC=abort` in a separate target directory, so it is
+//! ignored by default; the `panic-abort` CI job runs it with
+//! `cargo test --test panic_abort_crash_report -- --ignored`.
+
+#![allow(missing_docs)]
+
+use asupersync::runtime::{CrashReportConfig, JoinHandle, PanicStrategy, RuntimeBuilder};
+use std::path::PathBuf;
+use std::process::Command;
+
+fn report_path(dir: &tempfile::TempDir) -> PathBuf {
+    dir.path().join("crash-report.txt")
+}
+
+#[test]
+#[ignore = "rebuilds the example with panic=abort in a separate target dir"]
+fn abort_build_writes_crash_report_before_aborting() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = report_path(&dir);
+    let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/panic-abort");
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--example", "panic_abort_crash_report", "--"])
+        .arg(&path)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("CARGO_PROFILE_DEV_PANIC", "abort")
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .output()
+        .expect("run example");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "abort build must not exit cleanly");
+    assert!(stdout.contains("panic strategy: abort"), "stdout: {stdout}");
+    assert!(!stdout.contains("task panic contained"), "stdout: {stdout}");
+
+    let report = std::fs::read_to_string(&path).expect("crash report written");
+    assert!(report.starts_with("asupersync crash report\n"), "{report}");
+    assert!(report.contains("panic_strategy: abort"), "{report}");
+    assert!(report.contains("message: crash report example task panicked"), "{report}");
+    assert!(report.contains("location: examples/panic_abort_crash_report.rs"), "{report}");
+    assert!(report.contains("\nregions: "), "{report}");
+    assert!(report.contains("\nregion: "), "{report}");
+    assert!(report.contains("\nevents: "), "{report}");
+}
+
+#[test]
+fn unwind_build_contains_task_panics_without_crash_report() {
+    assert_eq!(PanicStrategy::current(), PanicStrategy::Unwind);
+    let dir = tempfile::tempdir().expect("tempdir");
+    let path = report_path(&dir);
+    let runtime = RuntimeBuilder::current_thread()
+        .crash_report(CrashReportConfig::new(&path))
+        .build()
+        .expect("runtime");
+
+    let handle: JoinHandle<()> = runtime.handle().spawn(async {
+        panic!("contained task panic");
+    });
+    let joined = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        runtime.block_on(handle);
+    }));
+    let payload = joined.expect_err("task panic surfaces at the join");
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or_default();
+    assert!(message.contains("contained task panic"), "payload: {message}");
+
+    // The runtime keeps serving tasks after the contained panic.
+    let value = runtime.block_on(runtime.handle().spawn(async { 7_u32 }));
+    assert_eq!(value, 7);
+    assert!(!path.exists(), "no crash report in unwinding builds");
+}
@@ -601,6 +601,7 @@ fn reply_to_remote_message(reply: &WireReply) -> Option<RemoteMessage> {
                 new_lease: Duration::from_millis(50),
                 current_state: parse_state(current_state),
                 node: NodeId::new(REMOTE_NODE),
+                timing: asupersync::remote::LeaseTiming::default(),
             },
         )),
         WireReply::ResultSuccess {
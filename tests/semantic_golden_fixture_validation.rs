@@ -98,17 +98,21 @@ fn build_contract_scenario(identity: &DualRunScenarioIdentity) -> Scenario {
         },
         chaos: ChaosSection::Off,
         network: NetworkSection::default(),
+        topology: Default::default(),
         faults: Vec::new(),
+        connect_faults: Default::default(),
         participants: Vec::new(),
         oracles: vec!["all".to_string()],
         cancellation: None,
         resource_caps: Default::default(),
+        limits: Default::default(),
         expected_invariants: vec![
             "quiescence".to_string(),
             "losers_drained".to_string(),
             "no_obligation_leaks".to_string(),
             "deterministic_replay".to_string(),
         ],
+        ordering: Vec::new(),
         minimization: Default::default(),
         golden_projection: Default::default(),
         include: Vec::new(),
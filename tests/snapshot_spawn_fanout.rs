@@ -0,0 +1,82 @@
+#![allow(missing_docs)]
+#![cfg(feature = "test-internals")]
+//! `examples/spawn_fanout.rs` in snapshot form.
+//!
+//! The example spawns ten members and folds their results. Here the same
+//! fan-out runs in the lab: a collector task joins each member in order and
+//! logs what it got, and the normalized log is compared against a committed
+//! snapshot. Rerun with `UPDATE_SNAPSHOTS=1` after an intentional change.
+
+use asupersync::assert_snapshot;
+use asupersync::cx::Cx;
+use asupersync::lab::{LabConfig, LabRuntime};
+use asupersync::observability::{LogEntry, TraceId};
+use asupersync::test_utils::{LogCapture, TraceSummary, init_test_logging};
+use asupersync::types::Budget;
+
+const MEMBERS: u32 = 10;
+
+fn run_fanout(seed: u64) -> (LabRuntime, LogCapture) {
+    let mut runtime = LabRuntime::new(LabConfig::new(seed));
+    let capture = LogCapture::install(&mut runtime);
+    let root = runtime.state.create_root_region(Budget::INFINITE);
+
+    let mut members = Vec::new();
+    for i in 0..MEMBERS {
+        let (task_id, handle) = runtime
+            .state
+            .create_task(root, Budget::INFINITE, async move { i })
+            .expect("spawn member");
+        runtime.scheduler.lock().schedule(task_id, 0);
+        members.push(handle);
+    }
+
+    let (collector, _handle) = runtime
+        .state
+        .create_task(root, Budget::INFINITE, async move {
+            let cx = Cx::current().expect("lab task context");
+            let trace_id = TraceId::from_cx(&cx).to_hex();
+            let mut total = 0;
+            for (member, mut handle) in members.into_iter().enumerate() {
+                let member_task = handle.task_id().to_string();
+                let value = handle.join(&cx).await.expect("member ok");
+                total += value;
+                cx.log(
+                    LogEntry::info("member joined")
+                        .with_field("member", member.to_string())
+                        .with_field("member_task", member_task)
+                        .with_field("value", value.to_string())
+                        .with_field("trace_id", trace_id.clone()),
+                );
+            }
+            cx.log(
+                LogEntry::info("fanout complete")
+                    .with_field("total", total.to_string())
+                    .with_field("trace_id", trace_id),
+            );
+            total
+        })
+        .expect("spawn collector");
+    runtime.scheduler.lock().schedule(collector, 0);
+    runtime.run_until_quiescent();
+    (runtime, capture)
+}
+
+#[test]
+fn spawn_fanout_log_snapshot() {
+    init_test_logging();
+    let (_runtime, capture) = run_fanout(0x5EED);
+    assert_snapshot!("spawn_fanout_logs", capture.render());
+}
+
+#[test]
+fn spawn_fanout_trace_summary_is_seed_stable() {
+    init_test_logging();
+    let (first, _) = run_fanout(0x5EED);
+    let (second, _) = run_fanout(0x5EED);
+    let summary = TraceSummary::from_lab(&first);
+    assert_eq!(summary, TraceSummary::from_lab(&second));
+    assert_eq!(summary.render(), TraceSummary::from_lab(&second).render());
+    assert_eq!(summary.event_counts.get("spawn"), Some(&11));
+    assert_eq!(summary.event_counts.get("complete"), Some(&11));
+}
@@ -0,0 +1,234 @@
+#![allow(warnings)]
+#![allow(clippy::all)]
+//! Loom model tests for the core synchronization primitives.
+//!
+//! Unlike `scheduler_loom.rs`, which models protocols in isolation, these
+//! tests drive the real `mpsc`, `oneshot`, `Semaphore`, and `TaskWakeState`
+//! types. Under `--cfg loom` those types take their atomics and mutexes from
+//! the crate's internal loom shim (`src/util/loom.rs`), so loom explores every
+//! interleaving of the production code.
+//!
+//! Run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --test sync_loom \
+//!     --features loom-tests,test-internals --release
+//! ```
+//!
+//! Bounded tests honour `LOOM_MAX_PREEMPTIONS` and default to a bound of 3,
+//! which keeps CI runs short. The mpsc wakeup test runs unbounded.
+
+#![cfg(all(loom, feature = "loom-tests"))]
+
+use asupersync::channel::{mpsc, oneshot};
+use asupersync::cx::Cx;
+use asupersync::record::task::TaskWakeState;
+use asupersync::sync::Semaphore;
+use loom::future::block_on;
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::thread;
+use std::sync::Arc;
+
+/// Runs `f` under loom with a CI-sized preemption bound unless the
+/// environment already sets one.
+fn model<F>(f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let mut builder = loom::model::Builder::new();
+    if builder.preemption_bound.is_none() {
+        builder.preemption_bound = Some(3);
+    }
+    builder.check(f);
+}
+
+// ============================================================================
+// mpsc: no lost wakeups
+// ============================================================================
+
+#[test]
+fn loom_mpsc_recv_wakes_on_send_exhaustive() {
+    loom::model(|| {
+        let (tx, mut rx) = mpsc::channel::<u32>(1);
+
+        let sender = thread::spawn(move || {
+            tx.try_send(7).expect("capacity available");
+        });
+
+        let cx = Cx::for_testing();
+        let value = block_on(rx.recv(&cx));
+        assert_eq!(value, Ok(7));
+        sender.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_mpsc_blocked_sender_wakes_on_recv() {
+    model(|| {
+        let (tx, mut rx) = mpsc::channel::<u32>(1);
+        tx.try_send(1).expect("first slot free");
+
+        let sender = thread::spawn(move || {
+            let cx = Cx::for_testing();
+            block_on(tx.send(&cx, 2)).expect("receiver alive");
+        });
+
+        let cx = Cx::for_testing();
+        assert_eq!(block_on(rx.recv(&cx)), Ok(1));
+        assert_eq!(block_on(rx.recv(&cx)), Ok(2));
+        sender.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_mpsc_sender_drop_wakes_receiver() {
+    model(|| {
+        let (tx, mut rx) = mpsc::channel::<u32>(1);
+
+        let sender = thread::spawn(move || drop(tx));
+
+        let cx = Cx::for_testing();
+        assert_eq!(block_on(rx.recv(&cx)), Err(mpsc::RecvError::Disconnected));
+        sender.join().unwrap();
+    });
+}
+
+// ============================================================================
+// Semaphore: permit accounting
+// ============================================================================
+
+#[test]
+fn loom_semaphore_concurrent_try_acquire_conserves_permits() {
+    model(|| {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let acquired = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let semaphore = Arc::clone(&semaphore);
+                let acquired = Arc::clone(&acquired);
+                thread::spawn(move || {
+                    if let Ok(permit) = semaphore.try_acquire(1) {
+                        let holders = acquired.fetch_add(1, Ordering::SeqCst) + 1;
+                        assert!(holders <= 1, "more holders than permits");
+                        acquired.fetch_sub(1, Ordering::SeqCst);
+                        drop(permit);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(semaphore.available_permits(), 1);
+    });
+}
+
+#[test]
+fn loom_semaphore_waiter_wakes_on_release() {
+    model(|| {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.try_acquire(1).expect("initial permit");
+
+        let waiter = {
+            let semaphore = Arc::clone(&semaphore);
+            thread::spawn(move || {
+                let cx = Cx::for_testing();
+                let permit = block_on(semaphore.acquire(&cx, 1)).expect("not closed");
+                drop(permit);
+            })
+        };
+
+        drop(permit);
+        waiter.join().unwrap();
+        assert_eq!(semaphore.available_permits(), 1);
+    });
+}
+
+// ============================================================================
+// oneshot: send/drop races
+// ============================================================================
+
+#[test]
+fn loom_oneshot_send_wakes_receiver() {
+    model(|| {
+        let (tx, mut rx) = oneshot::channel::<u32>();
+
+        let sender = thread::spawn(move || {
+            tx.send_blocking(5).expect("receiver alive");
+        });
+
+        let cx = Cx::for_testing();
+        assert_eq!(block_on(rx.recv(&cx)), Ok(5));
+        sender.join().unwrap();
+    });
+}
+
+#[test]
+fn loom_oneshot_sender_drop_wakes_receiver() {
+    model(|| {
+        let (tx, mut rx) = oneshot::channel::<u32>();
+
+        let sender = thread::spawn(move || drop(tx));
+
+        let cx = Cx::for_testing();
+        assert_eq!(block_on(rx.recv(&cx)), Err(oneshot::RecvError::Closed));
+        sender.join().unwrap();
+    });
+}
+
+/// Payload that counts its drops so the race can check exactly-once cleanup.
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn loom_oneshot_send_races_receiver_drop() {
+    model(|| {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = oneshot::channel::<Tracked>();
+
+        let receiver = thread::spawn(move || drop(rx));
+
+        let payload = Tracked(Arc::clone(&drops));
+        // Either outcome is legal; the payload must be dropped exactly once.
+        match tx.send_blocking(payload) {
+            Ok(()) => {}
+            Err(err) => drop(err),
+        }
+        receiver.join().unwrap();
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    });
+}
+
+// ============================================================================
+// TaskWakeState: wake dedup never loses a notification
+// ============================================================================
+
+#[test]
+fn loom_task_wake_state_notify_during_poll_is_observed() {
+    model(|| {
+        let state = Arc::new(TaskWakeState::new());
+
+        let notifier = {
+            let state = Arc::clone(&state);
+            thread::spawn(move || state.notify())
+        };
+
+        state.begin_poll();
+        let repoll = state.finish_poll();
+        let scheduled = notifier.join().unwrap();
+
+        // A wake that lands before or after the poll schedules the task; one
+        // that lands during the poll must be reported by finish_poll.
+        assert!(
+            scheduled || repoll,
+            "wake lost: neither scheduled nor repolled"
+        );
+    });
+}
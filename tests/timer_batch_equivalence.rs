@@ -0,0 +1,187 @@
+//! Batched timer operations fire exactly like their single-timer equivalents.
+//!
+//! Two virtual-clock drivers receive the same random schedule of
+//! registrations, deadline resets, cancellations, and clock advances. One
+//! applies it a timer at a time (`register`, `update`, `cancel`); the other
+//! through `TimerBatch` (`register_many`, `reset_many`, `cancel_many`). After
+//! every advance both must have fired the same timers.
+
+use asupersync::time::{TimerDriverHandle, TimerHandle, VirtualClock};
+use asupersync::types::Time;
+use proptest::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::task::{Wake, Waker};
+
+#[derive(Debug, Clone)]
+enum Op {
+    Register(Vec<u64>),
+    Reset(Vec<usize>, u64),
+    Cancel(Vec<usize>),
+    Advance(u64),
+}
+
+/// Deadline offsets in milliseconds, spanning the ready queue, every wheel
+/// level, and the overflow heap.
+fn offset_ms() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        4 => 0..300_u64,
+        4 => 300..70_000_u64,
+        2 => 70_000..20_000_000_u64,
+        1 => 90_000_000..200_000_000_u64,
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => prop::collection::vec(offset_ms(), 1..4).prop_map(Op::Register),
+        3 => (prop::collection::vec(any::<usize>(), 1..4), offset_ms())
+            .prop_map(|(targets, offset)| Op::Reset(targets, offset)),
+        1 => prop::collection::vec(any::<usize>(), 1..3).prop_map(Op::Cancel),
+        3 => prop_oneof![0..5_u64, 5..2_000_u64, 2_000..30_000_000_u64].prop_map(Op::Advance),
+    ]
+}
+
+struct LabelWaker {
+    label: usize,
+    fired: Arc<Mutex<Vec<usize>>>,
+}
+
+impl Wake for LabelWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.fired.lock().unwrap().push(self.label);
+    }
+}
+
+struct Harness {
+    clock: Arc<VirtualClock>,
+    timer: TimerDriverHandle,
+    fired: Arc<Mutex<Vec<usize>>>,
+    handles: Vec<TimerHandle>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let clock = Arc::new(VirtualClock::new());
+        Self {
+            timer: TimerDriverHandle::with_virtual_clock(Arc::clone(&clock)),
+            clock,
+            fired: Arc::new(Mutex::new(Vec::new())),
+            handles: Vec::new(),
+        }
+    }
+
+    fn waker(&self, label: usize) -> Waker {
+        Arc::new(LabelWaker {
+            label,
+            fired: Arc::clone(&self.fired),
+        })
+        .into()
+    }
+
+    fn deadline(&self, offset_ms: u64) -> Time {
+        self.clock.now().saturating_add_nanos(offset_ms * 1_000_000)
+    }
+
+    /// Maps arbitrary indices onto registered timers.
+    fn targets(&self, picks: &[usize]) -> Vec<usize> {
+        if self.handles.is_empty() {
+            return Vec::new();
+        }
+        picks.iter().map(|pick| pick % self.handles.len()).collect()
+    }
+
+    fn apply_singly(&mut self, op: &Op) {
+        match op {
+            Op::Register(offsets) => {
+                for &offset in offsets {
+                    let waker = self.waker(self.handles.len());
+                    let handle = self.timer.register(self.deadline(offset), waker);
+                    self.handles.push(handle);
+                }
+            }
+            Op::Reset(picks, offset) => {
+                let deadline = self.deadline(*offset);
+                for label in self.targets(picks) {
+                    let waker = self.waker(label);
+                    self.handles[label] = self.timer.update(&self.handles[label], deadline, waker);
+                }
+            }
+            Op::Cancel(picks) => {
+                for label in self.targets(picks) {
+                    let _ = self.timer.cancel(&self.handles[label]);
+                }
+            }
+            Op::Advance(ms) => self.clock.advance(ms * 1_000_000),
+        }
+    }
+
+    fn apply_batched(&mut self, op: &Op) {
+        let batch = self.timer.batch();
+        match op {
+            Op::Register(offsets) => {
+                let first = self.handles.len();
+                let entries = offsets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &offset)| (self.deadline(offset), self.waker(first + i)))
+                    .collect::<Vec<_>>();
+                let handles = batch.register_many(entries);
+                self.handles.extend(handles);
+            }
+            Op::Reset(picks, offset) => {
+                let handles = self
+                    .targets(picks)
+                    .into_iter()
+                    .map(|label| self.handles[label])
+                    .collect::<Vec<_>>();
+                let _ = batch.reset_many(&handles, self.deadline(*offset));
+            }
+            Op::Cancel(picks) => {
+                let handles = self
+                    .targets(picks)
+                    .into_iter()
+                    .map(|label| self.handles[label])
+                    .collect::<Vec<_>>();
+                let _ = batch.cancel_many(&handles);
+            }
+            Op::Advance(ms) => self.clock.advance(ms * 1_000_000),
+        }
+    }
+
+    /// Fires due timers and returns the labels woken, sorted.
+    fn fire(&self) -> Vec<usize> {
+        let _ = self.timer.process_timers();
+        let mut fired = std::mem::take(&mut *self.fired.lock().unwrap());
+        fired.sort_unstable();
+        fired
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn batched_timers_fire_like_single_timers(ops in prop::collection::vec(op(), 1..60)) {
+        let mut single = Harness::new();
+        let mut batched = Harness::new();
+
+        for (step, op) in ops.iter().enumerate() {
+            single.apply_singly(op);
+            batched.apply_batched(op);
+            if matches!(op, Op::Advance(_)) {
+                prop_assert_eq!(single.fire(), batched.fire(), "step {}: {:?}", step, op);
+            }
+            prop_assert_eq!(single.timer.pending_count(), batched.timer.pending_count());
+            prop_assert_eq!(single.timer.next_deadline(), batched.timer.next_deadline());
+        }
+
+        // Drain everything that is still armed.
+        single.clock.advance(400_000_000_000_000);
+        batched.clock.advance(400_000_000_000_000);
+        prop_assert_eq!(single.fire(), batched.fire());
+    }
+}
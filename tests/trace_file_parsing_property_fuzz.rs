@@ -6,7 +6,7 @@
 //! mostly hits early-rejection paths (wrong magic, too-short input). It
 //! does NOT systematically cover:
 //!   1. Valid header followed by truncation at every byte offset
-//!   2. Header with `version > TRACE_FILE_VERSION` (currently 2) — every
+//!   2. Header with `version > TRACE_FILE_VERSION` (currently 3) — every
 //!      future-version code must error with `UnsupportedVersion`
 //!   3. Valid magic + valid version + truncated metadata length / body
 //!
@@ -0,0 +1,146 @@
+//! Compression-ratio regression tests for framed zstd trace files.
+//!
+//! The fixture under `tests/fixtures/trace_compression/` is a checked-in soak
+//! trace dominated by small scheduling, timer and I/O events. These tests pin
+//! the ratios the framed writer reaches on it so that a change to the frame
+//! layout, the event encoding or the default level cannot quietly regress
+//! on-disk trace size:
+//!
+//! - **Large frames**: zstd with the default 64 KiB frames must stay at least
+//!   4x smaller than the uncompressed file (measured ~5.2x).
+//! - **Small frames + dictionary**: with 1 KiB frames each frame has little
+//!   history to draw on, so a dictionary trained on the same kind of events
+//!   must beat dictionary-less compression.
+//!
+//! Every variant must also round-trip the fixture exactly.
+
+#![cfg(feature = "trace-compression")]
+
+use asupersync::trace::framed::{DEFAULT_ZSTD_LEVEL, TraceDictionary};
+use asupersync::trace::replay::{ReplayEvent, TraceMetadata};
+use asupersync::trace::{
+    CompressionMode, TraceFileConfig, TraceReadOptions, TraceReader, write_trace,
+};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const MIN_LARGE_FRAME_RATIO: f64 = 4.0;
+const SMALL_FRAME_SIZE: usize = 1024;
+const DICTIONARY_SIZE: usize = 4096;
+
+#[derive(Deserialize)]
+struct SampleTrace {
+    metadata: TraceMetadata,
+    events: Vec<ReplayEvent>,
+}
+
+fn load_sample() -> SampleTrace {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/trace_compression/sample_soak_trace.json");
+    let json = std::fs::read_to_string(&path).expect("read sample trace fixture");
+    serde_json::from_str(&json).expect("parse sample trace fixture")
+}
+
+fn zstd_config(frame_size: usize) -> TraceFileConfig {
+    TraceFileConfig::new()
+        .with_compression(CompressionMode::Zstd {
+            level: DEFAULT_ZSTD_LEVEL,
+        })
+        .with_chunk_size(frame_size)
+}
+
+/// Writes the sample with `config` and returns the path and file size.
+fn write_sample(
+    dir: &TempDir,
+    name: &str,
+    sample: &SampleTrace,
+    config: TraceFileConfig,
+) -> (PathBuf, u64) {
+    let path = dir.path().join(name);
+    asupersync::trace::file::write_trace_with_config(
+        &path,
+        &sample.metadata,
+        &sample.events,
+        config,
+    )
+    .expect("write trace");
+    let size = std::fs::metadata(&path).expect("stat trace").len();
+    (path, size)
+}
+
+fn assert_round_trip(path: &Path, options: &TraceReadOptions, sample: &SampleTrace) {
+    let reader = TraceReader::open_with_options(path, options).expect("open trace");
+    assert_eq!(reader.metadata(), &sample.metadata);
+    assert_eq!(reader.load_all().expect("load trace"), sample.events);
+}
+
+#[test]
+fn sample_fixture_is_a_small_event_soak_trace() {
+    let sample = load_sample();
+    assert!(
+        sample.events.len() > 2_000,
+        "fixture shrank to {} events",
+        sample.events.len()
+    );
+}
+
+#[test]
+fn zstd_large_frames_meet_ratio_floor() {
+    let sample = load_sample();
+    let dir = TempDir::new().expect("temp dir");
+
+    let plain = dir.path().join("plain.trace");
+    write_trace(&plain, &sample.metadata, &sample.events).expect("write trace");
+    let plain_size = std::fs::metadata(&plain).expect("stat trace").len();
+
+    let (zstd, zstd_size) = write_sample(
+        &dir,
+        "zstd.trace",
+        &sample,
+        TraceFileConfig::default().with_compression(CompressionMode::Zstd {
+            level: DEFAULT_ZSTD_LEVEL,
+        }),
+    );
+    let ratio = plain_size as f64 / zstd_size as f64;
+    assert!(
+        ratio >= MIN_LARGE_FRAME_RATIO,
+        "zstd ratio regressed: {plain_size} -> {zstd_size} bytes ({ratio:.2}x, \
+         floor {MIN_LARGE_FRAME_RATIO}x)"
+    );
+
+    assert_round_trip(&zstd, &TraceReadOptions::new(), &sample);
+}
+
+#[test]
+fn dictionary_beats_plain_zstd_on_small_frames() {
+    let sample = load_sample();
+    let dir = TempDir::new().expect("temp dir");
+    let dictionary =
+        TraceDictionary::train(&sample.events, DICTIONARY_SIZE).expect("train dictionary");
+
+    let (plain, plain_size) = write_sample(
+        &dir,
+        "small-frames.trace",
+        &sample,
+        zstd_config(SMALL_FRAME_SIZE),
+    );
+    let (dict, dict_size) = write_sample(
+        &dir,
+        "small-frames-dict.trace",
+        &sample,
+        zstd_config(SMALL_FRAME_SIZE).with_dictionary(dictionary.clone()),
+    );
+    assert!(
+        dict_size < plain_size,
+        "dictionary did not help small frames: {dict_size} bytes with dictionary vs \
+         {plain_size} without"
+    );
+
+    assert_round_trip(&plain, &TraceReadOptions::new(), &sample);
+    assert_round_trip(
+        &dict,
+        &TraceReadOptions::new().with_dictionary(dictionary),
+        &sample,
+    );
+}
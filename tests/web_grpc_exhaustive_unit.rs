@@ -106,21 +106,22 @@ fn t52_unit_01_wildcard_route_matching() {
 }
 
 #[test]
-fn t52_unit_02_route_conflict_last_wins() {
-    init_test("t52_unit_02_route_conflict_last_wins");
+fn t52_unit_02_duplicate_route_rejected() {
+    init_test("t52_unit_02_duplicate_route_rejected");
 
-    test_section!("duplicate_route_first_handler_wins");
-    // In this router implementation, the first matching route wins (routes are
-    // checked in registration order; the first match is returned).
-    let router = Router::new()
+    test_section!("duplicate_route_rejected_at_registration");
+    // Registering the same pattern twice for one method is a conflict; the
+    // first registration stays in place and the second is reported.
+    let conflict = Router::new()
         .route("/dup", get(FnHandler::new(|| -> &'static str { "first" })))
-        .route("/dup", get(FnHandler::new(|| -> &'static str { "second" })));
-
-    let resp = router.handle(Request::new("GET", "/dup"));
-    let body = std::str::from_utf8(&resp.body).unwrap();
-    assert_eq!(body, "first", "first registered handler wins on conflict");
-
-    test_complete!("t52_unit_02_route_conflict_last_wins");
+        .try_route("/dup", get(FnHandler::new(|| -> &'static str { "second" })))
+        .err()
+        .expect("duplicate route is a conflict");
+    assert_eq!(conflict.method.as_deref(), Some("GET"));
+    assert_eq!(conflict.existing, "/dup");
+    assert_eq!(conflict.conflicting, "/dup");
+
+    test_complete!("t52_unit_02_duplicate_route_rejected");
 }
 
 #[test]
@@ -1293,7 +1294,7 @@ fn t511_coverage_summary() {
     let sections = [
         // T5.2 Router
         ("T52-01", "wildcard_route_matching"),
-        ("T52-02", "route_conflict_last_wins"),
+        ("T52-02", "duplicate_route_rejected"),
         ("T52-03", "empty_path_handling"),
         ("T52-04", "special_characters_in_paths"),
         ("T52-05", "nested_router_isolation"),
@@ -306,10 +306,10 @@ mod behavioral {
         let router = Router::new().route("/items/:id", get(ok_handler()));
 
         // An exotic path: encoded slash literal (not double-
-        // slash). The router does NOT decode %2F before
-        // matching, so this is a single literal segment.
+        // slash). The router splits before decoding %2F, so
+        // this is a single segment.
         let resp = dispatch(&router, "GET", "/items/%2F%2F");
-        // %2F%2F is NOT '//', so this is fine — :id="%2F%2F".
+        // %2F%2F is NOT '//', so this is fine — :id decodes to "//".
         // We're verifying the fix doesn't over-reject by
         // catching encoded slashes.
         assert_eq!(
@@ -6,9 +6,8 @@
 //!       contain `:id` (separate routes vs same-route duplicates).
 //!   (3) Wildcard route precedence when `*` appears mid-path.
 //!   (4) Path-traversal via percent-encoded slash decoded after
-//!       route-match — verify the router does NOT decode `%2F`
-//!       before matching, so a peer cannot bypass route
-//!       boundaries.
+//!       route-match — verify `%2F` never becomes a segment
+//!       boundary, so a peer cannot bypass route boundaries.
 //!
 //! Audit verdict: **SOUND.** All four vectors are defended:
 //!
@@ -39,23 +38,22 @@
 //!       priority than literal-only or param-only routes —
 //!       cannot shadow narrower protected paths.
 //!
-//!   (4) **Critical: the router does NOT decode percent-
-//!       encoding in the path.** The HTTP/1 codec parses
-//!       paths AS-IS (per `request_line_percent_encoding` test
-//!       at h1/request_line_tests.rs:209-229), and the router's
+//!   (4) **Critical: the router splits BEFORE it decodes.**
+//!       The HTTP/1 codec parses paths AS-IS (per
+//!       `request_line_percent_encoding` test at
+//!       h1/request_line_tests.rs:209-229), and the router's
 //!       split-on-`/` operates on literal bytes. So `%2F` in
-//!       the path stays as the three literal characters `%2F`
-//!       and does NOT split the path segment. A request to
-//!       `/api/foo%2F..%2Fadmin` matches `/api/:slug` with
-//!       `slug = "foo%2F..%2Fadmin"` — NOT `/api/foo/../admin`
-//!       which would have potentially matched a different
-//!       route. This closes the canonical "double-decode"
-//!       path-traversal class.
+//!       the path does NOT split the path segment, and literal
+//!       pattern segments compare against the raw bytes. A
+//!       request to `/api/foo%2F..%2Fadmin` matches `/api/:slug`
+//!       with the decoded capture `slug = "foo/../admin"` — NOT
+//!       `/api/foo/../admin` which would have potentially
+//!       matched a different route. This closes the canonical
+//!       "double-decode" path-traversal class.
 //!
-//!       If a downstream handler manually percent-decodes the
-//!       captured param, it gets a string containing `/`, but
-//!       by that point the routing decision is already made.
-//!       The captured param is opaque to the routing layer.
+//!       The captured param contains `/`, but by that point the
+//!       routing decision is already made. Handlers that build
+//!       file paths from it must still treat it as untrusted.
 //!
 //! Regression tests below pin (1)-(4).
 
@@ -345,11 +343,11 @@ fn wildcard_does_not_shadow_unrelated_route() {
 fn percent_encoded_slash_in_path_does_not_split_segment() {
     // Pin (4) audit-critical: `%2F` in the path is treated as
     // three literal characters by the router's split-on-`/`.
-    // It does NOT decode to `/` and split the segment.
+    // It is decoded only after splitting, inside the segment.
     //
     // This closes the double-decode path-traversal class. A
     // peer requesting `/api/foo%2F..%2Fadmin` matches
-    // `/api/:slug` with `slug = "foo%2F..%2Fadmin"` — NOT
+    // `/api/:slug` with `slug = "foo/../admin"` — NOT
     // `/api/foo/../admin` which would potentially match a
     // different route.
     let router = Router::new().route("/api/:slug", get(FnHandler::new(ok_handler)));
@@ -359,7 +357,7 @@ fn percent_encoded_slash_in_path_does_not_split_segment() {
         resp.status,
         StatusCode::OK,
         "%2F in path does NOT split the segment — matches single-segment \
-         route with the literal %2F bytes inside the captured param",
+         route with the decoded `/` inside the captured param",
     );
 }
 
@@ -416,11 +414,10 @@ fn dotdot_in_path_segment_does_not_traverse_at_router_level() {
 
 #[test]
 fn percent_encoded_slash_preserves_in_param_capture() {
-    // Pin (4) capture semantics: the captured param value
-    // contains the literal `%2F` bytes. Downstream handlers
-    // that percent-decode get the `/` character — but the
-    // routing decision is already locked in. The captured
-    // param is opaque from the router's perspective.
+    // Pin (4) capture semantics: the captured param value is
+    // percent-decoded after the split, so it contains `/` —
+    // but the routing decision is already locked in to the
+    // single-segment route.
     use asupersync::web::extract::Path;
     use asupersync::web::handler::FnHandler1;
 
@@ -440,11 +437,10 @@ fn percent_encoded_slash_preserves_in_param_capture() {
     let _ = router.handle(Request::new("GET", "/path/foo%2Fbar%2Fbaz"));
     let recovered = captured.lock().unwrap().clone().unwrap();
     assert_eq!(
-        recovered, "foo%2Fbar%2Fbaz",
-        "the captured `:opaque` param contains the LITERAL %2F bytes — \
-         the router does NOT pre-decode. Handlers that need decoded \
-         values must percent-decode explicitly, knowing the route \
-         decision is already final.",
+        recovered, "foo/bar/baz",
+        "the captured `:opaque` param is decoded after routing — \
+         the single segment `foo%2Fbar%2Fbaz` still matched the \
+         one-parameter route.",
     );
 }
 